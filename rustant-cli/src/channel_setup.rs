@@ -8,11 +8,13 @@
 //! 5. Validate credentials via test API call
 //! 6. Store secrets in OS keyring
 //! 7. Save channel config to `.rustant/config.toml`
-//! 8. Run a connection test
+//!
+//! The setup wizard (`rustant setup`) reuses these flows through
+//! [`configure_channel`] and [`send_test_message`].
 
 use dialoguer::{Input, Password, Select};
-use rustant_core::credentials::{CredentialStore, KeyringCredentialStore};
-use std::path::Path;
+use rustant_core::credentials::{CredentialStore, configured_store};
+use std::path::{Path, PathBuf};
 
 /// A channel option presented during the setup wizard.
 #[derive(Debug, Clone)]
@@ -53,6 +55,11 @@ pub fn available_channels() -> Vec<ChannelChoice> {
             display_name: "SMS (Twilio)",
             description: "SMS via Twilio account",
         },
+        ChannelChoice {
+            name: "signal",
+            display_name: "Signal",
+            description: "Signal via signal-cli, linked as a secondary device",
+        },
         ChannelChoice {
             name: "imessage",
             display_name: "iMessage",
@@ -61,24 +68,62 @@ pub fn available_channels() -> Vec<ChannelChoice> {
     ]
 }
 
+/// A channel configuration collected by one of the setup flows.
+///
+/// Nothing is written to disk until [`save_channel_setup`] is called, so the
+/// setup wizard can validate a channel before committing it to config.
+#[derive(Debug, Clone)]
+pub struct ChannelSetup {
+    /// Key under `[channels]` (e.g., "slack").
+    pub name: &'static str,
+    /// Human-readable label, including the auth flow used.
+    pub label: &'static str,
+    /// Serialized channel config.
+    pub config: toml::Value,
+}
+
+impl ChannelSetup {
+    fn new(
+        name: &'static str,
+        label: &'static str,
+        config: &impl serde::Serialize,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            name,
+            label,
+            config: toml::Value::try_from(config)?,
+        })
+    }
+
+    /// A sensible recipient for the validation test message, if the config
+    /// names one (Slack default channel, first allowed Telegram chat, or the
+    /// email or Signal account itself).
+    pub fn default_test_recipient(&self) -> Option<String> {
+        let value = match self.name {
+            "slack" => self.config.get("default_channel")?.as_str()?.to_string(),
+            "telegram" => self
+                .config
+                .get("allowed_chat_ids")?
+                .as_array()?
+                .first()?
+                .as_integer()?
+                .to_string(),
+            "email" => self.config.get("from_address")?.as_str()?.to_string(),
+            // Messages to your own number land in "Note to Self".
+            "signal" => self.config.get("phone_number")?.as_str()?.to_string(),
+            _ => return None,
+        };
+        (!value.is_empty()).then_some(value)
+    }
+}
+
 /// Run the interactive channel setup wizard.
 ///
 /// If `channel` is `None`, shows a selection menu. Otherwise jumps directly
 /// to the named channel's wizard.
 pub async fn run_channel_setup(workspace: &Path, channel: Option<&str>) -> anyhow::Result<()> {
     let channel_name = match channel {
-        Some(name) => {
-            let channels = available_channels();
-            if !channels.iter().any(|c| c.name == name) {
-                let valid: Vec<&str> = channels.iter().map(|c| c.name).collect();
-                anyhow::bail!(
-                    "Unknown channel '{}'. Valid channels: {}",
-                    name,
-                    valid.join(", ")
-                );
-            }
-            name.to_string()
-        }
+        Some(name) => name.to_string(),
         None => {
             println!("\n  Rustant Channel Setup\n");
             let channels = available_channels();
@@ -95,20 +140,84 @@ pub async fn run_channel_setup(workspace: &Path, channel: Option<&str>) -> anyho
         }
     };
 
-    match channel_name.as_str() {
-        "slack" => setup_slack(workspace).await,
-        "discord" => setup_discord(workspace).await,
-        "telegram" => setup_telegram(workspace).await,
-        "email" => setup_email(workspace).await,
-        "sms" => setup_sms(workspace).await,
-        "imessage" => setup_imessage(workspace).await,
-        _ => anyhow::bail!("Unknown channel: {}", channel_name),
+    let setup = configure_channel(&channel_name).await?;
+    let config_path = save_channel_setup(workspace, &setup)?;
+    println!(
+        "\n  {} setup complete! Config saved to {}",
+        setup.label,
+        config_path.display()
+    );
+
+    Ok(())
+}
+
+/// Run the named channel's interactive flow without writing any config.
+pub async fn configure_channel(name: &str) -> anyhow::Result<ChannelSetup> {
+    match name {
+        "slack" => setup_slack().await,
+        "discord" => setup_discord().await,
+        "telegram" => setup_telegram().await,
+        "email" => setup_email().await,
+        "sms" => setup_sms().await,
+        "signal" => setup_signal().await,
+        "imessage" => setup_imessage().await,
+        _ => {
+            let valid: Vec<&str> = available_channels().iter().map(|c| c.name).collect();
+            anyhow::bail!(
+                "Unknown channel '{}'. Valid channels: {}",
+                name,
+                valid.join(", ")
+            );
+        }
+    }
+}
+
+/// Write a collected channel config to `.rustant/config.toml`, leaving all
+/// other channels and settings untouched.
+pub fn save_channel_setup(workspace: &Path, setup: &ChannelSetup) -> anyhow::Result<PathBuf> {
+    rustant_core::config::update_channel_config(workspace, setup.name, setup.config.clone())
+}
+
+/// Connect the configured channel, send a test message to `recipient`, and
+/// disconnect again. Returns a short description of the result.
+pub async fn send_test_message(setup: &ChannelSetup, recipient: &str) -> anyhow::Result<String> {
+    use rustant_core::channels::{ChannelMessage, ChannelType, ChannelUser};
+    use rustant_core::config::ChannelsConfig;
+
+    let mut table = toml::map::Map::new();
+    table.insert(setup.name.to_string(), setup.config.clone());
+    let channels: ChannelsConfig = toml::Value::Table(table).try_into()?;
+    let channel_type = ChannelType::from_name(setup.name)
+        .ok_or_else(|| anyhow::anyhow!("Unknown channel type '{}'", setup.name))?;
+
+    let mut mgr = rustant_core::channels::build_channel_manager(&channels);
+    if mgr.channel_count() == 0 {
+        anyhow::bail!("{} is not available on this platform", setup.label);
+    }
+    for (_, result) in mgr.connect_all().await {
+        result.map_err(|e| anyhow::anyhow!("Connection failed: {}", e))?;
     }
+
+    let sender = ChannelUser::new("rustant", channel_type);
+    let msg = ChannelMessage::text(
+        channel_type,
+        recipient,
+        sender,
+        "Rustant setup: this channel is connected and working.",
+    );
+    let sent = mgr.send_to(setup.name, msg).await;
+    mgr.disconnect_all().await;
+    sent.map_err(|e| anyhow::anyhow!("Test message failed: {}", e))?;
+
+    Ok(format!(
+        "Connected and delivered a test message to {}",
+        recipient
+    ))
 }
 
 // ── Slack ──────────────────────────────────────────────────────────────────
 
-async fn setup_slack(workspace: &Path) -> anyhow::Result<()> {
+async fn setup_slack() -> anyhow::Result<ChannelSetup> {
     use rustant_core::channels::slack::SlackConfig;
     use rustant_core::oauth::AuthMethod;
     use rustant_core::secret_ref::SecretRef;
@@ -135,7 +244,7 @@ async fn setup_slack(workspace: &Path) -> anyhow::Result<()> {
         .interact()?
         == 0;
 
-    let cred_store = KeyringCredentialStore::new();
+    let cred_store = configured_store();
     let bot_token: String;
     let auth_method: AuthMethod;
 
@@ -216,19 +325,12 @@ async fn setup_slack(workspace: &Path) -> anyhow::Result<()> {
         auth_method,
     };
 
-    let config_val = toml::Value::try_from(&slack_config)?;
-    let config_path = rustant_core::config::update_channel_config(workspace, "slack", config_val)?;
-    println!(
-        "\n  Slack setup complete! Config saved to {}",
-        config_path.display()
-    );
-
-    Ok(())
+    ChannelSetup::new("slack", "Slack", &slack_config)
 }
 
 // ── Discord ────────────────────────────────────────────────────────────────
 
-async fn setup_discord(workspace: &Path) -> anyhow::Result<()> {
+async fn setup_discord() -> anyhow::Result<ChannelSetup> {
     use rustant_core::channels::discord::DiscordConfig;
     use rustant_core::oauth::AuthMethod;
 
@@ -254,7 +356,7 @@ async fn setup_discord(workspace: &Path) -> anyhow::Result<()> {
         .interact()?
         == 0;
 
-    let cred_store = KeyringCredentialStore::new();
+    let cred_store = configured_store();
     let bot_token: String;
     let auth_method: AuthMethod;
 
@@ -321,21 +423,14 @@ async fn setup_discord(workspace: &Path) -> anyhow::Result<()> {
         auth_method,
     };
 
-    let config_val = toml::Value::try_from(&discord_config)?;
-    let config_path =
-        rustant_core::config::update_channel_config(workspace, "discord", config_val)?;
-    println!(
-        "\n  Discord setup complete! Config saved to {}",
-        config_path.display()
-    );
-
-    Ok(())
+    ChannelSetup::new("discord", "Discord", &discord_config)
 }
 
 // ── Telegram ───────────────────────────────────────────────────────────────
 
-async fn setup_telegram(workspace: &Path) -> anyhow::Result<()> {
+async fn setup_telegram() -> anyhow::Result<ChannelSetup> {
     use rustant_core::channels::telegram::TelegramConfig;
+    use rustant_core::secret_ref::SecretRef;
 
     println!("\n  Telegram Bot Setup\n");
     println!("  Create a Telegram bot via BotFather.\n");
@@ -362,9 +457,9 @@ async fn setup_telegram(workspace: &Path) -> anyhow::Result<()> {
     println!("  {}", bot_info);
 
     // Store in credential store
-    let cred_store = KeyringCredentialStore::new();
+    let cred_store = configured_store();
     cred_store
-        .store_key("telegram_bot_token", &bot_token)
+        .store_key("channel:telegram:bot_token", &bot_token)
         .map_err(|e| anyhow::anyhow!("Failed to store token: {}", e))?;
 
     // Optional: allowed chat IDs
@@ -384,26 +479,19 @@ async fn setup_telegram(workspace: &Path) -> anyhow::Result<()> {
     };
 
     let telegram_config = TelegramConfig {
-        bot_token: bot_token.clone(),
+        bot_token: SecretRef::keychain("channel:telegram:bot_token"),
         allowed_chat_ids,
         polling_timeout_secs: 30,
     };
 
-    let config_val = toml::Value::try_from(&telegram_config)?;
-    let config_path =
-        rustant_core::config::update_channel_config(workspace, "telegram", config_val)?;
-    println!(
-        "\n  Telegram setup complete! Config saved to {}",
-        config_path.display()
-    );
-
-    Ok(())
+    ChannelSetup::new("telegram", "Telegram", &telegram_config)
 }
 
 // ── Email (Gmail) ──────────────────────────────────────────────────────────
 
-async fn setup_email(workspace: &Path) -> anyhow::Result<()> {
+async fn setup_email() -> anyhow::Result<ChannelSetup> {
     use rustant_core::channels::email::{EmailAuthMethod, EmailConfig};
+    use rustant_core::secret_ref::SecretRef;
 
     println!("\n  Email (Gmail) Setup\n");
     println!("  Connect Rustant to Gmail for sending and receiving email.\n");
@@ -416,7 +504,7 @@ async fn setup_email(workspace: &Path) -> anyhow::Result<()> {
         .interact()?
         == 0;
 
-    let cred_store = KeyringCredentialStore::new();
+    let cred_store = configured_store();
 
     if use_oauth {
         println!();
@@ -471,20 +559,18 @@ async fn setup_email(workspace: &Path) -> anyhow::Result<()> {
             smtp_host: "smtp.gmail.com".to_string(),
             smtp_port: 587,
             username: email_address.clone(),
-            password: token.access_token,
+            // The token is loaded (and refreshed) from the credential store
+            // at connection time rather than copied into the config.
+            password: SecretRef::default(),
             password_env: None,
             from_address: email_address,
             allowed_senders: Vec::new(),
             auth_method: EmailAuthMethod::XOAuth2,
+            oauth_provider: Some("gmail".to_string()),
+            ..EmailConfig::default()
         };
 
-        let config_val = toml::Value::try_from(&email_config)?;
-        let config_path =
-            rustant_core::config::update_channel_config(workspace, "email", config_val)?;
-        println!(
-            "\n  Email (Gmail OAuth) setup complete! Config saved to {}",
-            config_path.display()
-        );
+        ChannelSetup::new("email", "Email (Gmail OAuth)", &email_config)
     } else {
         println!();
         println!("  For Gmail with app password:");
@@ -523,7 +609,7 @@ async fn setup_email(workspace: &Path) -> anyhow::Result<()> {
 
         // Store password in credential store
         cred_store
-            .store_key("email_password", &password)
+            .store_key("channel:email:password", &password)
             .map_err(|e| anyhow::anyhow!("Failed to store password: {}", e))?;
 
         let email_config = EmailConfig {
@@ -532,28 +618,21 @@ async fn setup_email(workspace: &Path) -> anyhow::Result<()> {
             smtp_host,
             smtp_port,
             username: email_address.clone(),
-            password,
+            password: SecretRef::keychain("channel:email:password"),
             password_env: None,
             from_address: email_address,
             allowed_senders: Vec::new(),
             auth_method: EmailAuthMethod::Password,
+            ..EmailConfig::default()
         };
 
-        let config_val = toml::Value::try_from(&email_config)?;
-        let config_path =
-            rustant_core::config::update_channel_config(workspace, "email", config_val)?;
-        println!(
-            "\n  Email (IMAP) setup complete! Config saved to {}",
-            config_path.display()
-        );
+        ChannelSetup::new("email", "Email (IMAP)", &email_config)
     }
-
-    Ok(())
 }
 
 // ── SMS (Twilio) ───────────────────────────────────────────────────────────
 
-async fn setup_sms(workspace: &Path) -> anyhow::Result<()> {
+async fn setup_sms() -> anyhow::Result<ChannelSetup> {
     println!("\n  SMS (Twilio) Setup\n");
     println!("  Connect Rustant to Twilio for sending and receiving SMS.\n");
     println!("  How to get Twilio credentials:");
@@ -586,7 +665,7 @@ async fn setup_sms(workspace: &Path) -> anyhow::Result<()> {
     println!("  {}", account_info);
 
     // Store secrets in credential store
-    let cred_store = KeyringCredentialStore::new();
+    let cred_store = configured_store();
     cred_store
         .store_key("twilio_account_sid", &account_sid)
         .map_err(|e| anyhow::anyhow!("Failed to store SID: {}", e))?;
@@ -602,22 +681,129 @@ async fn setup_sms(workspace: &Path) -> anyhow::Result<()> {
         polling_interval_ms: 5000,
     };
 
-    let config_val = toml::Value::try_from(&sms_config)?;
-    let config_path = rustant_core::config::update_channel_config(workspace, "sms", config_val)?;
-    println!(
-        "\n  SMS (Twilio) setup complete! Config saved to {}",
-        config_path.display()
-    );
+    ChannelSetup::new("sms", "SMS (Twilio)", &sms_config)
+}
 
-    Ok(())
+// ── Signal ─────────────────────────────────────────────────────────────────
+
+async fn setup_signal() -> anyhow::Result<ChannelSetup> {
+    use rustant_core::channels::signal::{SignalConfig, check_version, signal_cli_version};
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    println!("\n  Signal Setup\n");
+    println!("  Rustant talks to Signal through signal-cli, linked to your phone");
+    println!("  as a secondary device (like Signal Desktop).\n");
+    println!("  Requirements:");
+    println!("    1. Install signal-cli: https://github.com/AsamK/signal-cli");
+    println!("    2. Keep your phone nearby to scan a QR code");
+    println!();
+
+    let signal_cli_path: String = Input::new()
+        .with_prompt("Path to signal-cli")
+        .default("signal-cli".to_string())
+        .interact_text()?;
+
+    let version = signal_cli_version(&signal_cli_path)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    check_version(&version).map_err(|e| anyhow::anyhow!(e))?;
+    println!("  Found {}", version);
+
+    let link = Select::new()
+        .with_prompt("How should Rustant use Signal?")
+        .items(&[
+            "Link to my phone (scan a QR code)",
+            "Use an account already set up in signal-cli",
+        ])
+        .default(0)
+        .interact()?;
+
+    let mut phone_number = String::new();
+    if link == 0 {
+        let mut child = tokio::process::Command::new(&signal_cli_path)
+            .args(["link", "--name", "Rustant"])
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Failed to run signal-cli link: {}", e))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("signal-cli link produced no output"))?;
+        let mut lines = BufReader::new(stdout).lines();
+        while let Some(line) = lines.next_line().await? {
+            if let Some(uri) = signal_link_uri(&line) {
+                println!("\n{}", rustant_core::gateway::qr_terminal(uri)?);
+                println!("  On your phone: Signal → Settings → Linked devices → Link new device,");
+                println!("  then scan the code above. Waiting for the link to complete...\n");
+            } else if let Some(number) = signal_linked_number(&line) {
+                phone_number = number.to_string();
+            }
+        }
+        let status = child.wait().await?;
+        if !status.success() {
+            anyhow::bail!("Linking failed: signal-cli link exited with {}", status);
+        }
+        if !phone_number.is_empty() {
+            println!("  Linked as {}", phone_number);
+        }
+    }
+    if phone_number.is_empty() {
+        phone_number = Input::new()
+            .with_prompt("Your Signal phone number (E.164, e.g., +15551234567)")
+            .interact_text()?;
+    }
+
+    let socket: String = Input::new()
+        .with_prompt(
+            "Socket of a running signal-cli daemon (empty to let Rustant start signal-cli)",
+        )
+        .default(String::new())
+        .show_default(false)
+        .interact_text()?;
+
+    let contacts_input: String = Input::new()
+        .with_prompt("Allowed contacts (comma-separated numbers, or empty for all)")
+        .default(String::new())
+        .show_default(false)
+        .interact_text()?;
+
+    let signal_config = SignalConfig {
+        phone_number,
+        signal_cli_path,
+        socket_path: (!socket.trim().is_empty()).then(|| PathBuf::from(socket.trim())),
+        allowed_contacts: contacts_input
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect(),
+        ..Default::default()
+    };
+
+    ChannelSetup::new("signal", "Signal", &signal_config)
+}
+
+/// The device-link URI printed by `signal-cli link`.
+fn signal_link_uri(line: &str) -> Option<&str> {
+    let line = line.trim();
+    (line.starts_with("sgnl://") || line.starts_with("tsdevice:")).then_some(line)
+}
+
+/// The account number from `signal-cli link`'s "Associated with: +1…" line.
+fn signal_linked_number(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix("Associated with:")
+        .map(str::trim)
+        .filter(|n| n.starts_with('+'))
 }
 
 // ── iMessage ───────────────────────────────────────────────────────────────
 
-async fn setup_imessage(workspace: &Path) -> anyhow::Result<()> {
+async fn setup_imessage() -> anyhow::Result<ChannelSetup> {
     #[cfg(not(target_os = "macos"))]
     {
-        let _ = workspace;
         anyhow::bail!(
             "iMessage is only available on macOS.\n\
              This machine does not appear to be running macOS."
@@ -643,10 +829,9 @@ async fn setup_imessage(workspace: &Path) -> anyhow::Result<()> {
             .interact()?;
 
         if signed_in != 0 {
-            println!(
-                "\n  Please sign into iMessage in the Messages app first, then re-run this setup."
+            anyhow::bail!(
+                "Please sign into iMessage in the Messages app first, then re-run this setup."
             );
-            return Ok(());
         }
 
         // Check access
@@ -659,22 +844,14 @@ async fn setup_imessage(workspace: &Path) -> anyhow::Result<()> {
             polling_interval_ms: 5000,
         };
 
-        let config_val = toml::Value::try_from(&imessage_config)?;
-        let config_path =
-            rustant_core::config::update_channel_config(workspace, "imessage", config_val)?;
-        println!(
-            "\n  iMessage setup complete! Config saved to {}",
-            config_path.display()
-        );
-
-        Ok(())
+        ChannelSetup::new("imessage", "iMessage", &imessage_config)
     }
 }
 
 // ── Validation functions ───────────────────────────────────────────────────
 
 /// Validate a Slack bot token by calling the auth.test API.
-async fn validate_slack_token(token: &str) -> anyhow::Result<String> {
+pub(crate) async fn validate_slack_token(token: &str) -> anyhow::Result<String> {
     let client = reqwest::Client::new();
     let response = client
         .post("https://slack.com/api/auth.test")
@@ -702,7 +879,7 @@ async fn validate_slack_token(token: &str) -> anyhow::Result<String> {
 }
 
 /// Validate a Discord bot token by calling the /users/@me API.
-async fn validate_discord_token(token: &str) -> anyhow::Result<String> {
+pub(crate) async fn validate_discord_token(token: &str) -> anyhow::Result<String> {
     let client = reqwest::Client::new();
     let response = client
         .get("https://discord.com/api/v10/users/@me")
@@ -728,7 +905,7 @@ async fn validate_discord_token(token: &str) -> anyhow::Result<String> {
 }
 
 /// Validate a Telegram bot token by calling the getMe API.
-async fn validate_telegram_token(token: &str) -> anyhow::Result<String> {
+pub(crate) async fn validate_telegram_token(token: &str) -> anyhow::Result<String> {
     let client = reqwest::Client::new();
     let url = format!("https://api.telegram.org/bot{}/getMe", token);
     let response = client
@@ -867,18 +1044,58 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_available_channels_has_all_six() {
+    fn test_available_channels_has_all_seven() {
         let channels = available_channels();
-        assert_eq!(channels.len(), 6);
+        assert_eq!(channels.len(), 7);
         let names: Vec<&str> = channels.iter().map(|c| c.name).collect();
         assert!(names.contains(&"slack"));
         assert!(names.contains(&"discord"));
         assert!(names.contains(&"telegram"));
         assert!(names.contains(&"email"));
         assert!(names.contains(&"sms"));
+        assert!(names.contains(&"signal"));
         assert!(names.contains(&"imessage"));
     }
 
+    #[test]
+    fn test_signal_link_output_parsing() {
+        assert_eq!(
+            signal_link_uri("sgnl://linkdevice?uuid=abc&pub_key=def\n"),
+            Some("sgnl://linkdevice?uuid=abc&pub_key=def")
+        );
+        assert_eq!(signal_link_uri("Linking..."), None);
+        assert_eq!(
+            signal_linked_number("Associated with: +15551234567"),
+            Some("+15551234567")
+        );
+        assert_eq!(signal_linked_number("Associated with: "), None);
+    }
+
+    #[test]
+    fn test_default_test_recipient() {
+        use rustant_core::channels::telegram::TelegramConfig;
+
+        let telegram = TelegramConfig {
+            allowed_chat_ids: vec![42, 7],
+            ..Default::default()
+        };
+        let setup = ChannelSetup::new("telegram", "Telegram", &telegram).unwrap();
+        assert_eq!(setup.default_test_recipient().as_deref(), Some("42"));
+
+        let setup = ChannelSetup::new("telegram", "Telegram", &TelegramConfig::default()).unwrap();
+        assert_eq!(setup.default_test_recipient(), None);
+
+        let email = rustant_core::channels::email::EmailConfig {
+            from_address: "me@example.com".into(),
+            ..Default::default()
+        };
+        let setup = ChannelSetup::new("email", "Email (IMAP)", &email).unwrap();
+        assert_eq!(
+            setup.default_test_recipient().as_deref(),
+            Some("me@example.com")
+        );
+    }
+
     #[test]
     fn test_slack_token_format_validation() {
         assert!(validate_slack_token_format("xoxb-123-abc").is_ok());
@@ -3,37 +3,86 @@
 use crate::AuthAction;
 use crate::BrowserAction;
 use crate::CanvasAction;
+use crate::CdcCommand;
 use crate::ChannelAction;
 use crate::Commands;
 use crate::ConfigAction;
 use crate::CronAction;
+use crate::DevAction;
+use crate::EvalAction;
+use crate::GatewayAction;
+use crate::IndexAction;
 use crate::PluginAction;
+use crate::PolicyAction;
+use crate::SessionsAction;
 use crate::SkillAction;
 use crate::SlackCommand;
+use crate::StatsReport;
+use crate::TrustAction;
 use crate::UpdateAction;
 use crate::VoiceAction;
 use crate::WorkflowAction;
+use crate::WorkspaceAction;
 use std::path::Path;
 
 /// Handle a CLI subcommand.
 pub async fn handle_command(command: Commands, workspace: &Path) -> anyhow::Result<()> {
     match command {
         Commands::Config { action } => handle_config(action, workspace).await,
-        Commands::Setup => crate::setup::run_setup(workspace).await,
+        Commands::Setup { section } => {
+            crate::setup::run_setup_section(workspace, section.as_deref()).await
+        }
         Commands::Init => handle_init(workspace).await,
-        Commands::Resume { session } => handle_resume(session.as_deref(), workspace).await,
-        Commands::Sessions { limit } => handle_sessions(limit, workspace),
+        Commands::Doctor { json } => crate::doctor::run(workspace, json).await,
+        Commands::Resume {
+            session,
+            continue_task,
+        } => handle_resume(session.as_deref(), continue_task, workspace).await,
+        Commands::Sessions { limit, action } => match action {
+            None => handle_sessions(limit, workspace),
+            Some(SessionsAction::Replay {
+                session,
+                reexecute,
+                turn,
+            }) => handle_sessions_replay(&session, reexecute, turn, workspace).await,
+            Some(SessionsAction::Export {
+                session,
+                output,
+                metadata_only,
+            }) => handle_sessions_export(session.as_deref(), output, metadata_only, workspace),
+        },
         Commands::Channel { action } => handle_channel(action, workspace).await,
         Commands::Auth { action } => handle_auth(action, workspace).await,
         Commands::Workflow { action } => handle_workflow(action, workspace).await,
         Commands::Cron { action } => handle_cron(action, workspace).await,
         Commands::Voice { action } => handle_voice(action).await,
         Commands::Browser { action } => handle_browser(action, workspace).await,
-        Commands::Ui { port } => handle_ui(port).await,
+        Commands::Ui { port } => handle_ui(port, workspace).await,
+        Commands::Gateway { action } => handle_gateway(action, workspace).await,
         Commands::Canvas { action } => handle_canvas(action).await,
-        Commands::Skill { action } => handle_skill(action).await,
+        Commands::Skill { action } => handle_skill(action, workspace).await,
         Commands::Plugin { action } => handle_plugin(action).await,
-        Commands::Update { action } => handle_update(action).await,
+        Commands::Update { action } => handle_update(action, workspace).await,
+        Commands::Eval { action } => handle_eval(action, workspace).await,
+        Commands::Policy { action } => handle_policy(action, workspace),
+        Commands::Cdc { action } => handle_cdc(action, workspace).await,
+        Commands::Briefing {
+            week_ahead,
+            deliver,
+        } => {
+            let period = if week_ahead {
+                rustant_core::BriefingPeriod::WeekAhead
+            } else {
+                rustant_core::BriefingPeriod::Day
+            };
+            handle_briefing(period, deliver, workspace).await
+        }
+        Commands::Index { action } => handle_index(action, workspace),
+        Commands::Stats { report, days, sql } => {
+            handle_stats(report, days, sql.as_deref(), workspace)
+        }
+        Commands::Workspace { action } => handle_workspace(action, workspace),
+        Commands::Dev { action } => handle_dev(action),
     }
 }
 
@@ -68,12 +117,84 @@ async fn handle_config(action: ConfigAction, workspace: &Path) -> anyhow::Result
             println!("{}", toml_str);
             Ok(())
         }
+        ConfigAction::Trust { action } => {
+            let store = rustant_core::trust::default_trust_path()
+                .ok_or_else(|| anyhow::anyhow!("Could not determine the rustant data directory"))?;
+            handle_config_trust(action, workspace, &store)
+        }
+        ConfigAction::RotateSessionKey => handle_rotate_session_key(workspace).await,
+    }
+}
+
+/// `rustant config rotate-session-key`: re-encrypt sessions in a blocking
+/// task while reporting progress here.
+async fn handle_rotate_session_key(workspace: &Path) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let root = workspace.to_path_buf();
+    let rotation = tokio::task::spawn_blocking(move || {
+        let store = rustant_core::credentials::configured_store();
+        rustant_core::session_manager::rotate_session_key(&root, &*store, |done, total| {
+            let _ = tx.send((done, total));
+        })
+    });
+    while let Some((done, total)) = rx.recv().await {
+        print!("\rRe-encrypting sessions: {}/{}", done, total);
+        std::io::stdout().flush()?;
+    }
+    println!();
+    let rewritten = rotation
+        .await?
+        .map_err(|e| anyhow::anyhow!("Session key rotation failed: {}", e))?;
+    println!(
+        "Session key rotated; {} file(s) re-encrypted. The previous key has been removed.",
+        rewritten
+    );
+    Ok(())
+}
+
+fn handle_config_trust(action: TrustAction, workspace: &Path, store: &Path) -> anyhow::Result<()> {
+    use rustant_core::trust::{TrustLedger, TrustPolicy, TrustScope};
+
+    let mut ledger = TrustLedger::load(store)?;
+    match action {
+        TrustAction::Show {
+            tool,
+            workspace: only_workspace,
+        } => {
+            let config =
+                rustant_core::config::load_config(Some(workspace), None).unwrap_or_default();
+            let trust_config = config.safety.adaptive_trust.unwrap_or_default();
+            if !trust_config.enabled {
+                println!("Adaptive trust is disabled ([safety.adaptive_trust] enabled = false).");
+            }
+            let scope = match (tool, only_workspace) {
+                (Some(tool), _) => TrustScope::Tool(tool),
+                (None, Some(path)) => TrustScope::Workspace(path),
+                (None, None) => TrustScope::All,
+            };
+            let policy = TrustPolicy::from_config(&trust_config);
+            println!("{}", ledger.report(&policy, &scope, chrono::Utc::now()));
+            println!("\nStored in {}", store.display());
+            Ok(())
+        }
+        TrustAction::Reset { target } => {
+            let current = workspace.display().to_string();
+            let scope = TrustScope::parse(&target.join(" "), &current).ok_or_else(|| {
+                anyhow::anyhow!("Expected a tool name, `workspace [path]`, or `all`")
+            })?;
+            let removed = ledger.reset(&scope);
+            ledger.save(store)?;
+            println!("Reset {} trust score(s).", removed);
+            Ok(())
+        }
     }
 }
 
 async fn handle_init(workspace: &Path) -> anyhow::Result<()> {
     use rustant_core::project_detect::{
-        detect_project, example_tasks, recommended_allowed_commands,
+        attributed_allowed_commands, detect_project, example_tasks, recommended_allowed_commands,
     };
 
     println!("\n  \x1b[1mRustant Smart Init\x1b[0m\n");
@@ -96,6 +217,18 @@ async fn handle_init(workspace: &Path) -> anyhow::Result<()> {
     if info.has_ci {
         println!("  CI:           \x1b[36mdetected\x1b[0m");
     }
+    if info.is_monorepo() {
+        println!("  Subprojects:");
+        for sub in &info.subprojects {
+            println!(
+                "    \x1b[36m{:<14}\x1b[0m {:<10} {} ({})",
+                sub.name,
+                sub.project_type.to_string(),
+                sub.path.display(),
+                sub.manifest
+            );
+        }
+    }
     println!();
 
     // Step 2: Check for existing config
@@ -161,7 +294,7 @@ async fn handle_init(workspace: &Path) -> anyhow::Result<()> {
             println!();
         } else {
             println!("  No API keys detected. Starting provider setup...\n");
-            if let Err(e) = crate::setup::run_setup(workspace).await {
+            if let Err(e) = crate::setup::run_provider_setup(workspace).await {
                 eprintln!("  Setup failed: {}. Generating config with defaults.\n", e);
             } else {
                 // Reload config after setup wizard
@@ -178,6 +311,9 @@ async fn handle_init(workspace: &Path) -> anyhow::Result<()> {
     // Step 4: Apply project-specific safety settings
     let allowed_cmds = recommended_allowed_commands(&info);
     config.safety.allowed_commands = allowed_cmds;
+    if info.is_monorepo() {
+        config.subprojects = info.subprojects.clone();
+    }
 
     // Set approval mode based on git status
     config.safety.approval_mode = if info.has_git && info.git_clean {
@@ -198,6 +334,12 @@ async fn handle_init(workspace: &Path) -> anyhow::Result<()> {
             .allowed_paths
             .extend(["tests/**".to_string(), "docs/**".to_string()]);
     }
+    for sub in info.subprojects.iter().filter(|s| !s.is_root()) {
+        config
+            .safety
+            .allowed_paths
+            .push(format!("{}/**", sub.path.display()));
+    }
 
     // Step 5: Write config
     std::fs::create_dir_all(&config_dir)?;
@@ -223,7 +365,31 @@ async fn handle_init(workspace: &Path) -> anyhow::Result<()> {
     println!();
 
     // Build/test commands
-    if !info.build_commands.is_empty() || !info.test_commands.is_empty() {
+    if info.is_monorepo() {
+        println!("  \x1b[33mDetected project commands:\x1b[0m");
+        for sub in &info.subprojects {
+            println!("    \x1b[1m{}\x1b[0m ({}):", sub.name, sub.path.display());
+            for cmd in &sub.build_commands {
+                println!("      Build: {}", cmd);
+            }
+            for cmd in &sub.test_commands {
+                println!("      Test:  {}", cmd);
+            }
+            for cmd in &sub.lint_commands {
+                println!("      Lint:  {}", cmd);
+            }
+        }
+        println!();
+        println!("  \x1b[33mAllowed commands:\x1b[0m");
+        for (cmd, sources) in attributed_allowed_commands(&info) {
+            if sources.is_empty() {
+                println!("    {}", cmd);
+            } else {
+                println!("    {:<14} ({})", cmd, sources.join(", "));
+            }
+        }
+        println!();
+    } else if !info.build_commands.is_empty() || !info.test_commands.is_empty() {
         println!("  \x1b[33mDetected project commands:\x1b[0m");
         for cmd in &info.build_commands {
             println!("    Build: {}", cmd);
@@ -256,8 +422,12 @@ async fn handle_init(workspace: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn handle_resume(session: Option<&str>, workspace: &Path) -> anyhow::Result<()> {
-    let mut mgr = rustant_core::SessionManager::new(workspace)
+async fn handle_resume(
+    session: Option<&str>,
+    continue_task: bool,
+    workspace: &Path,
+) -> anyhow::Result<()> {
+    let mut mgr = open_sessions(workspace)
         .map_err(|e| anyhow::anyhow!("Failed to initialize session manager: {}", e))?;
 
     let (memory, continuation) = if let Some(query) = session {
@@ -295,13 +465,33 @@ async fn handle_resume(session: Option<&str>, workspace: &Path) -> anyhow::Resul
 
     let callback = std::sync::Arc::new(crate::repl::CliCallback::new(false));
     let mut agent = rustant_core::Agent::new(provider, config, callback);
+    let mut registry = rustant_tools::registry::ToolRegistry::new();
+    rustant_tools::register_builtin_tools(&mut registry, workspace.to_path_buf());
+    crate::repl::register_agent_tools_from_registry(&mut agent, &registry, workspace);
     *agent.memory_mut() = memory;
+    agent.set_artifacts(mgr.active_artifacts());
+    agent.set_task_runs(mgr.active_task_runs());
 
     // Inject the continuation context
     agent
         .memory_mut()
         .add_message(rustant_core::types::Message::system(continuation));
 
+    if continue_task {
+        match agent.continue_task().await {
+            Ok(result) => println!(
+                "\x1b[90m  [{} iterations, {} tokens, ${:.4}]\x1b[0m",
+                result.iterations,
+                result.total_usage.total(),
+                result.total_cost.total()
+            ),
+            Err(e) => println!("\x1b[31mError: {}\x1b[0m", e),
+        }
+        if let Err(e) = mgr.set_task_runs(agent.task_runs()) {
+            tracing::warn!("Failed to record task runs: {}", e);
+        }
+    }
+
     // Run in interactive REPL mode
     println!("  Type your next instruction to continue, or /quit to exit.\n");
 
@@ -342,28 +532,39 @@ async fn handle_resume(session: Option<&str>, workspace: &Path) -> anyhow::Resul
 }
 
 fn handle_sessions(limit: usize, workspace: &Path) -> anyhow::Result<()> {
-    let mgr = rustant_core::SessionManager::new(workspace)
+    let mgr = open_sessions(workspace)
         .map_err(|e| anyhow::anyhow!("Failed to initialize session manager: {}", e))?;
 
-    let sessions = mgr.list_sessions(limit);
+    let sessions = mgr.index().list_tree(limit);
     if sessions.is_empty() {
         println!("No saved sessions found.");
         println!("Sessions are saved automatically when using the agent.");
         return Ok(());
     }
 
-    println!("\x1b[1mSaved Sessions\x1b[0m (most recent first):\n");
-    for entry in &sessions {
+    println!("\x1b[1mSaved Sessions\x1b[0m (most recent first, branches under their parent):\n");
+    for (depth, entry) in &sessions {
         let status = if entry.completed {
             "\x1b[32mdone\x1b[0m"
         } else {
             "\x1b[33min progress\x1b[0m"
         };
         let goal = entry.last_goal.as_deref().unwrap_or("(no goal recorded)");
+        let indent = "    ".repeat(*depth);
+        let marker = if *depth > 0 { "└─ " } else { "" };
+        let fork = entry
+            .branch
+            .as_ref()
+            .map(|b| format!(" forked at turn {}", b.turn))
+            .unwrap_or_default();
 
-        println!("  \x1b[1;36m{}\x1b[0m  [{}]", entry.name, status);
         println!(
-            "    Goal: {}",
+            "  {}{}\x1b[1;36m{}\x1b[0m  [{}]{}",
+            indent, marker, entry.name, status, fork
+        );
+        println!(
+            "  {}  Goal: {}",
+            indent,
             if goal.len() > 60 {
                 format!("{}...", &goal[..60])
             } else {
@@ -371,7 +572,8 @@ fn handle_sessions(limit: usize, workspace: &Path) -> anyhow::Result<()> {
             }
         );
         println!(
-            "    Messages: {} | Tokens: {} | Updated: {}",
+            "  {}  Messages: {} | Tokens: {} | Updated: {}",
+            indent,
             entry.message_count,
             entry.total_tokens,
             entry.updated_at.format("%Y-%m-%d %H:%M UTC")
@@ -380,9 +582,214 @@ fn handle_sessions(limit: usize, workspace: &Path) -> anyhow::Result<()> {
     }
 
     println!("Resume with: \x1b[36mrustant resume [name]\x1b[0m");
+    println!("Replay with: \x1b[36mrustant sessions replay <name>\x1b[0m");
+    println!(
+        "Branch with: \x1b[36m/fork <turn>\x1b[0m and \x1b[36m/compare <branch>\x1b[0m in the REPL"
+    );
+    Ok(())
+}
+
+/// Open this workspace's sessions, encrypted at rest unless
+/// `[sessions] encrypt_at_rest = false`.
+pub(crate) fn open_sessions(
+    workspace: &Path,
+) -> Result<rustant_core::SessionManager, rustant_core::error::MemoryError> {
+    let config = rustant_core::config::load_config(Some(workspace), None).unwrap_or_default();
+    rustant_core::SessionManager::open(workspace, &config.sessions)
+}
+
+/// `rustant sessions export`: decrypted sessions, or only the plaintext
+/// index metadata (which needs no key) with `--metadata-only`.
+fn handle_sessions_export(
+    session: Option<&str>,
+    output: Option<std::path::PathBuf>,
+    metadata_only: bool,
+    workspace: &Path,
+) -> anyhow::Result<()> {
+    let export = if metadata_only {
+        let sessions_dir = workspace.join(".rustant").join("sessions");
+        let index = rustant_core::SessionIndex::load(&sessions_dir)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        let entries: Vec<_> = index
+            .entries
+            .iter()
+            .filter(|e| {
+                session.is_none_or(|q| {
+                    e.id.to_string() == q || e.name.to_lowercase().starts_with(&q.to_lowercase())
+                })
+            })
+            .map(|e| {
+                serde_json::json!({
+                    "id": e.id,
+                    "name": e.name,
+                    "created_at": e.created_at,
+                    "updated_at": e.updated_at,
+                    "message_count": e.message_count,
+                    "total_tokens": e.total_tokens,
+                    "completed": e.completed,
+                    "tags": e.tags,
+                })
+            })
+            .collect();
+        serde_json::Value::Array(entries)
+    } else {
+        let mgr = open_sessions(workspace).map_err(|e| {
+            anyhow::anyhow!(
+                "{}\nUse `rustant sessions export --metadata-only` to export what is readable without the key.",
+                e
+            )
+        })?;
+        match session {
+            Some(query) => mgr
+                .export_session(query)
+                .map_err(|e| anyhow::anyhow!("{}", e))?,
+            None => serde_json::Value::Array(
+                mgr.index()
+                    .entries
+                    .iter()
+                    .map(|e| mgr.export_session(&e.id.to_string()))
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| anyhow::anyhow!("{}", e))?,
+            ),
+        }
+    };
+
+    let json = serde_json::to_string_pretty(&export)?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, json)?;
+            println!("Exported to {}", path.display());
+        }
+        None => println!("{}", json),
+    }
     Ok(())
 }
 
+async fn handle_sessions_replay(
+    session: &str,
+    reexecute: bool,
+    turn: Option<usize>,
+    workspace: &Path,
+) -> anyhow::Result<()> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    let mgr = open_sessions(workspace)
+        .map_err(|e| anyhow::anyhow!("Failed to initialize session manager: {}", e))?;
+    let (entry, recording) = mgr
+        .load_recording(session)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    if reexecute {
+        let config = rustant_core::config::load_config(Some(workspace), None)
+            .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+        let provider = rustant_core::create_provider(&config.llm)
+            .map_err(|e| anyhow::anyhow!("LLM provider init failed: {}", e))?;
+        let callback = std::sync::Arc::new(rustant_core::agent::NoOpCallback);
+        let mut agent = rustant_core::Agent::new(provider, config, callback);
+        let mut registry = rustant_tools::registry::ToolRegistry::new();
+        rustant_tools::register_builtin_tools(&mut registry, workspace.to_path_buf());
+        crate::repl::register_agent_tools_from_registry(&mut agent, &registry, workspace);
+
+        println!(
+            "Re-executing {} turn(s) of '{}' with {}...",
+            recording.turns.len(),
+            entry.name,
+            agent.brain().model_name()
+        );
+        let report = agent
+            .reexecute_recording(&recording)
+            .await
+            .map_err(|e| anyhow::anyhow!("Re-execution failed: {}", e))?;
+        println!("{}", report);
+        return Ok(());
+    }
+
+    let mut replay = rustant_core::replay::SessionReplay::new(recording)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    if let Some(turn) = turn {
+        replay
+            .seek(turn.saturating_sub(1))
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+    }
+
+    println!(
+        "\x1b[1mReplaying\x1b[0m \x1b[1;36m{}\x1b[0m ({} turns)\n",
+        entry.name,
+        replay.total_turns()
+    );
+
+    // Piped output: print every turn and stop.
+    if !std::io::stdin().is_terminal() {
+        loop {
+            println!("{}\n", replay.render_current());
+            if replay.step_forward().is_none() {
+                return Ok(());
+            }
+        }
+    }
+
+    println!(
+        "Keys: [Enter/n] next  [p] previous  [g <turn>] jump  [d [file]] dump prompt  [q] quit\n"
+    );
+    println!("{}", replay.render_current());
+
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!(
+            "\n\x1b[2m[{}/{}]>\x1b[0m ",
+            replay.position() + 1,
+            replay.total_turns()
+        );
+        std::io::stdout().flush()?;
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        let line = line?;
+        let mut parts = line.trim().splitn(2, char::is_whitespace);
+        let command = parts.next().unwrap_or("");
+        let arg = parts.next().map(str::trim).filter(|a| !a.is_empty());
+
+        match command {
+            "" | "n" => {
+                if replay.step_forward().is_some() {
+                    println!("{}", replay.render_current());
+                } else {
+                    println!("Already at the last turn.");
+                }
+            }
+            "p" => {
+                if replay.step_backward().is_some() {
+                    println!("{}", replay.render_current());
+                } else {
+                    println!("Already at the first turn.");
+                }
+            }
+            "g" => match arg.and_then(|a| a.parse::<usize>().ok()) {
+                Some(n) if n >= 1 => match replay.seek(n - 1) {
+                    Ok(_) => println!("{}", replay.render_current()),
+                    Err(e) => println!("{}", e),
+                },
+                _ => println!("Usage: g <turn> (1-{})", replay.total_turns()),
+            },
+            "d" => {
+                let path = arg.map(std::path::PathBuf::from).unwrap_or_else(|| {
+                    std::path::PathBuf::from(format!("turn-{}-prompt.json", replay.position() + 1))
+                });
+                match replay.dump_current_prompt(&path) {
+                    Ok(()) => println!("Prompt written to {}", path.display()),
+                    Err(e) => println!("Failed to dump prompt: {}", e),
+                }
+            }
+            "q" | "quit" => return Ok(()),
+            other => println!(
+                "Unknown key '{}'. Use n, p, g <turn>, d [file] or q.",
+                other
+            ),
+        }
+    }
+}
+
 pub async fn handle_channel(action: ChannelAction, workspace: &Path) -> anyhow::Result<()> {
     let config = rustant_core::config::load_config(Some(workspace), None)
         .map_err(|e| anyhow::anyhow!("Failed to load config: {}", e))?;
@@ -411,6 +818,108 @@ pub async fn handle_channel(action: ChannelAction, workspace: &Path) -> anyhow::
             Ok(())
         }
         ChannelAction::Slack { action } => handle_slack(action).await,
+        ChannelAction::RouteTest {
+            channel,
+            from,
+            text,
+            channel_name,
+            at,
+        } => {
+            use rustant_core::channels::{
+                ChannelMessage, ChannelRouter, ChannelType, ChannelUser, MessageClassifier,
+                RoutingInput, RuleOutcome,
+            };
+
+            let channel_type = ChannelType::from_name(&channel)
+                .ok_or_else(|| anyhow::anyhow!("Unknown channel type '{}'", channel))?;
+            let routing = channels_config.routing.unwrap_or_default();
+            let router = match ChannelRouter::from_config(&routing) {
+                Ok(router) => router,
+                Err(errors) => {
+                    println!("Routing rules are invalid:");
+                    for error in &errors {
+                        println!("  - {}", error);
+                    }
+                    anyhow::bail!("{} routing rule error(s)", errors.len());
+                }
+            };
+
+            let mut sender = ChannelUser::new(&from, channel_type);
+            sender.display_name = Some(from.clone());
+            let channel_id = channel_name.clone().unwrap_or_else(|| "route-test".into());
+            let mut msg = ChannelMessage::text(channel_type, &channel_id, sender, &text);
+            if let Some(name) = &channel_name {
+                msg.metadata.insert("channel_name".into(), name.clone());
+            }
+
+            let intelligence = config.intelligence.unwrap_or_default();
+            let classifier_config = intelligence
+                .channels
+                .get(&channel_type.to_string())
+                .cloned()
+                .unwrap_or(intelligence.defaults);
+            let classified = MessageClassifier::new(classifier_config).classify(&msg);
+            let mut input = RoutingInput::new(&msg);
+            input.category = Some(&classified.message_type);
+            if let Some(at) = &at {
+                input.time = chrono::NaiveTime::parse_from_str(at, "%H:%M")
+                    .map_err(|_| anyhow::anyhow!("Invalid --at '{}' (expected HH:MM)", at))?;
+            }
+
+            let trace = router.trace(&input);
+            println!(
+                "Message: [{}] {}{}: {}",
+                channel_type,
+                channel_name
+                    .as_deref()
+                    .map(|n| format!("{} ", n))
+                    .unwrap_or_default(),
+                from,
+                text
+            );
+            println!(
+                "Category: {:?} (classifier priority {:?})",
+                classified.message_type, classified.priority
+            );
+            println!("Evaluated at {}\n", input.time.format("%H:%M"));
+            if trace.rules.is_empty() {
+                println!("No routing rules configured ([channels.routing] in config).");
+            }
+            for rule in &trace.rules {
+                let outcome = match &rule.outcome {
+                    RuleOutcome::Matched => "MATCHED".to_string(),
+                    RuleOutcome::NotMatched { failed } => format!("no match ({} failed)", failed),
+                    RuleOutcome::Skipped => "skipped".to_string(),
+                };
+                println!("  {:>4}  {:<24} {}", rule.priority, rule.rule, outcome);
+            }
+            println!();
+            match &trace.target {
+                Some(target) if trace.used_default => {
+                    println!("Target: {} (default agent)", target)
+                }
+                Some(target) => println!("Target: {}", target),
+                None => println!("Target: none (no rule matched and no default_agent)"),
+            }
+            if let Some(priority) = trace.priority {
+                println!("Priority: {:?}", priority);
+            }
+
+            // Screened against a copy of the focus state; nothing is held.
+            let mut focus = rustant_core::focus::FocusState::load(workspace);
+            let status = focus.active.as_ref().map(|b| b.status_line());
+            match focus.screen(&classified, &channel, &config.focus, chrono::Utc::now()) {
+                rustant_core::focus::FocusDecision::Deliver => {}
+                rustant_core::focus::FocusDecision::Hold => println!(
+                    "Focus: held for the end-of-block digest ({})",
+                    status.unwrap_or_default()
+                ),
+                rustant_core::focus::FocusDecision::Interrupt(reason) => {
+                    println!("Focus: delivered through the focus block ({:?})", reason)
+                }
+            }
+            Ok(())
+        }
         ChannelAction::Test { name } => {
             let mut mgr = rustant_core::channels::build_channel_manager(&channels_config);
             let names = mgr.channel_names();
@@ -452,14 +961,13 @@ pub async fn handle_channel(action: ChannelAction, workspace: &Path) -> anyhow::
 }
 
 pub async fn handle_auth(action: AuthAction, workspace: &Path) -> anyhow::Result<()> {
-    use rustant_core::credentials::KeyringCredentialStore;
+    use rustant_core::credentials::{CredentialStore, configured_store};
     use rustant_core::oauth;
 
-    use rustant_core::credentials::CredentialStore;
+    let cred_store = configured_store();
 
-    let cred_store = KeyringCredentialStore::new();
-
-    const CHANNEL_PROVIDERS: &[&str] = &["slack", "discord", "teams", "whatsapp", "gmail"];
+    const CHANNEL_PROVIDERS: &[&str] =
+        &["slack", "discord", "teams", "whatsapp", "gmail", "outlook"];
 
     match action {
         AuthAction::Status => {
@@ -556,6 +1064,7 @@ pub async fn handle_auth(action: AuthAction, workspace: &Path) -> anyhow::Result
                         "teams" => "TEAMS_CLIENT_ID and TEAMS_CLIENT_SECRET",
                         "whatsapp" => "WHATSAPP_APP_ID and WHATSAPP_APP_SECRET",
                         "gmail" => "GMAIL_OAUTH_CLIENT_ID and GMAIL_OAUTH_CLIENT_SECRET",
+                        "outlook" => "OUTLOOK_OAUTH_CLIENT_ID and OUTLOOK_OAUTH_CLIENT_SECRET",
                         _ => "the required environment variables",
                     };
                     anyhow::anyhow!(
@@ -670,7 +1179,152 @@ pub async fn handle_auth(action: AuthAction, workspace: &Path) -> anyhow::Result
 
             Ok(())
         }
+
+        AuthAction::Migrate {
+            from,
+            to,
+            keep_source,
+            dry_run,
+        } => handle_auth_migrate(&from, &to, keep_source, dry_run, workspace),
+    }
+}
+
+/// `rustant auth migrate`: copy every known credential from one backend to
+/// another and report what moved.
+fn handle_auth_migrate(
+    from: &str,
+    to: &str,
+    keep_source: bool,
+    dry_run: bool,
+    workspace: &Path,
+) -> anyhow::Result<()> {
+    use rustant_core::credentials::{
+        CredentialStore, CredentialStoreKind, EnvCredentialStore, FileCredentialStore,
+        FileStoreKey, KeyringCredentialStore,
+    };
+    use std::sync::Arc;
+
+    let config = rustant_core::config::load_config(Some(workspace), None)
+        .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+    let file_config = config.credential_file.clone().unwrap_or_default();
+    let known = rustant_core::config::known_credentials(&config);
+    let mut accounts: Vec<String> = known.iter().map(|(account, _)| account.clone()).collect();
+    accounts.push(rustant_core::encryption::session_key_account(workspace));
+    accounts.push(rustant_core::encryption::previous_session_key_account(
+        workspace,
+    ));
+
+    let from = from.to_lowercase();
+    let to = to.to_lowercase();
+    if from == to {
+        anyhow::bail!("--from and --to name the same store ('{}')", from);
+    }
+
+    let open =
+        |name: &str, accounts: &mut Vec<String>| -> anyhow::Result<Arc<dyn CredentialStore>> {
+            match name {
+                "keyring" => {
+                    if !KeyringCredentialStore::is_available() {
+                        anyhow::bail!("The OS keychain is not available on this machine");
+                    }
+                    Ok(Arc::new(KeyringCredentialStore::new()))
+                }
+                "file" => {
+                    let key = FileStoreKey::resolve(&file_config)?;
+                    let store = FileCredentialStore::open(file_config.resolved_path(), key)?;
+                    for account in store.accounts()? {
+                        if !accounts.contains(&account) {
+                            accounts.push(account);
+                        }
+                    }
+                    Ok(Arc::new(store))
+                }
+                other => anyhow::bail!(
+                    "Unknown credential store '{}'. Use: env, keyring, file",
+                    other
+                ),
+            }
+        };
+
+    let env_vars: Vec<(String, String)> = known
+        .iter()
+        .filter_map(|(account, var)| var.clone().map(|var| (account.clone(), var)))
+        .collect();
+    let source: Arc<dyn CredentialStore> = if from == "env" {
+        Arc::new(EnvCredentialStore::new(env_vars.clone()))
+    } else {
+        open(&from, &mut accounts)?
+    };
+    if to == "env" {
+        anyhow::bail!("Credentials cannot be migrated into environment variables");
+    }
+    let destination = open(&to, &mut Vec::new())?;
+
+    if dry_run {
+        let pending: Vec<&String> = accounts.iter().filter(|a| source.has_key(a)).collect();
+        println!(
+            "Would migrate {} credential(s) from {} to {}:",
+            pending.len(),
+            from,
+            to
+        );
+        for account in pending {
+            println!("  {}", account);
+        }
+        return Ok(());
+    }
+
+    // Environment variables can only be unset by the user.
+    let keep_source = keep_source || from == "env";
+    let result = rustant_core::secret_ref::migrate_credentials(
+        &accounts,
+        source.as_ref(),
+        destination.as_ref(),
+        keep_source,
+    );
+
+    for account in &result.moved {
+        match env_vars.iter().find(|(a, _)| a == account) {
+            Some((_, var)) if from == "env" => println!("  {} (from ${})", account, var),
+            _ => println!("  {}", account),
+        }
+    }
+    for error in &result.errors {
+        println!("  error: {}", error);
+    }
+    println!("{}", result);
+
+    if !result.moved.is_empty() {
+        if from == "env" {
+            println!();
+            println!("The environment variables above can now be unset.");
+            for account in result.moved.iter().filter(|a| a.starts_with("channel:")) {
+                println!(
+                    "  Point the config at the store: \"keychain:{}\" instead of \"env:...\"",
+                    account
+                );
+            }
+        }
+        let kind = if to == "file" {
+            CredentialStoreKind::File
+        } else {
+            CredentialStoreKind::Keyring
+        };
+        if config.credential_store != kind && config.credential_store != CredentialStoreKind::Auto {
+            println!();
+            println!(
+                "Set `credential_store = \"{}\"` in config to use the migrated credentials.",
+                to
+            );
+        } else if to == "file" && config.credential_store == CredentialStoreKind::Auto {
+            println!();
+            println!(
+                "`credential_store = \"auto\"` prefers the OS keychain; set it to \"file\" to read the migrated credentials."
+            );
+        }
     }
+
+    Ok(())
 }
 
 pub async fn handle_workflow(action: WorkflowAction, _workspace: &Path) -> anyhow::Result<()> {
@@ -708,11 +1362,27 @@ pub async fn handle_workflow(action: WorkflowAction, _workspace: &Path) -> anyho
                 for (i, step) in wf.steps.iter().enumerate() {
                     let gate_str = if step.gate.is_some() { " [gated]" } else { "" };
                     println!("  {}. {} (tool: {}){}", i + 1, step.id, step.tool, gate_str);
+                    for reference in step.references() {
+                        println!("       <- {}", reference);
+                    }
+                    for output in &step.outputs {
+                        println!("       -> {} [{}]", output.name, output.output_type);
+                    }
                 }
                 if !wf.outputs.is_empty() {
                     println!("\nOutputs:");
                     for output in &wf.outputs {
-                        println!("  {}", output.name);
+                        let type_str = output
+                            .output_type
+                            .as_deref()
+                            .map(|t| format!(" [{}]", t))
+                            .unwrap_or_default();
+                        println!("  {}{}", output.name, type_str);
+                        for reference in
+                            rustant_core::workflow::templates::extract_expressions(&output.value)
+                        {
+                            println!("       <- {}", reference);
+                        }
                     }
                 }
                 Ok(())
@@ -723,23 +1393,10 @@ pub async fn handle_workflow(action: WorkflowAction, _workspace: &Path) -> anyho
             }
         },
         WorkflowAction::Run { name, input } => {
-            let _wf = rustant_core::get_builtin(&name)
+            let wf = rustant_core::get_builtin(&name)
                 .ok_or_else(|| anyhow::anyhow!("Workflow '{}' not found", name))?;
-
-            let mut inputs = std::collections::HashMap::new();
-            for kv in &input {
-                if let Some((key, value)) = kv.split_once('=') {
-                    inputs.insert(
-                        key.to_string(),
-                        serde_json::Value::String(value.to_string()),
-                    );
-                } else {
-                    return Err(anyhow::anyhow!(
-                        "Invalid input format '{}', expected key=value",
-                        kv
-                    ));
-                }
-            }
+            rustant_core::validate_workflow(&wf)?;
+            let inputs = rustant_core::workflow::parse_inputs(&wf, &input)?;
 
             println!("Starting workflow '{}'...", name);
             println!("  (Workflow execution requires an active agent session)");
@@ -769,6 +1426,11 @@ async fn handle_cron(action: CronAction, workspace: &Path) -> anyhow::Result<()>
     let config = rustant_core::config::load_config(Some(workspace), None)
         .map_err(|e| anyhow::anyhow!("Failed to load config: {}", e))?;
     let scheduler_config = config.scheduler.unwrap_or_default();
+    let briefing_jobs = config
+        .briefing
+        .as_ref()
+        .map(|b| b.cron_jobs())
+        .unwrap_or_default();
 
     // State file for persisting cron jobs across CLI invocations
     let state_dir = workspace.join(".rustant").join("cron");
@@ -784,12 +1446,14 @@ async fn handle_cron(action: CronAction, workspace: &Path) -> anyhow::Result<()>
         }
         // Fall back to config-defined jobs
         let mut scheduler = rustant_core::CronScheduler::new();
-        for job_config in &scheduler_config.cron_jobs {
+        for job_config in scheduler_config.cron_jobs.iter().chain(&briefing_jobs) {
             let _ = scheduler.add_job(job_config.clone());
         }
         scheduler
     };
 
+    let load_history = || rustant_core::JobHistory::load(&state_dir, scheduler_config.history_runs);
+
     // Save scheduler state to disk
     let save_scheduler = |scheduler: &rustant_core::CronScheduler| -> anyhow::Result<()> {
         std::fs::create_dir_all(&state_dir)?;
@@ -803,11 +1467,20 @@ async fn handle_cron(action: CronAction, workspace: &Path) -> anyhow::Result<()>
     match action {
         CronAction::List => {
             let scheduler = load_scheduler();
-            let jobs = scheduler.list_jobs();
+            // Jobs the agent scheduled live in the REPL's scheduler state.
+            let (authored, _) =
+                rustant_core::scheduler::load_state(&workspace.join(".rustant").join("scheduler"));
+            let mut jobs = scheduler.list_jobs();
+            jobs.extend(authored.list_jobs().into_iter().filter(|j| {
+                j.config.origin.is_some() && scheduler.get_job(&j.config.name).is_none()
+            }));
+            let kill_switch = rustant_core::KillSwitch::load(workspace);
+            let sessions = open_sessions(workspace).ok();
             if jobs.is_empty() {
                 println!("No cron jobs configured.");
                 println!("Add jobs via config or: rustant cron add <name> <schedule> <task>");
             } else {
+                let history = load_history();
                 println!("Cron jobs ({}):", jobs.len());
                 for job in &jobs {
                     let enabled = if job.config.enabled {
@@ -819,10 +1492,74 @@ async fn handle_cron(action: CronAction, workspace: &Path) -> anyhow::Result<()>
                         .next_run
                         .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
                         .unwrap_or_else(|| "N/A".to_string());
+                    let tag = job
+                        .config
+                        .workspace
+                        .as_deref()
+                        .map(|w| format!(" workspace={}", workspace_label(w)))
+                        .unwrap_or_default();
+                    let last = history
+                        .last(&job.config.name)
+                        .map(|run| {
+                            format!(
+                                " last={} ({})",
+                                run.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                                run.outcome
+                            )
+                        })
+                        .unwrap_or_default();
+                    let marker = if job.config.origin.is_some() {
+                        " [agent]"
+                    } else {
+                        ""
+                    };
+                    let killed = if kill_switch.is_killed(&job.config.name) {
+                        " KILLED"
+                    } else {
+                        ""
+                    };
                     println!(
-                        "  {} [{}] schedule=\"{}\" task=\"{}\" next={}",
-                        job.config.name, enabled, job.config.schedule, job.config.task, next
+                        "  {}{} [{}]{} schedule=\"{}\" task=\"{}\" next={}{}{}",
+                        job.config.name,
+                        marker,
+                        enabled,
+                        killed,
+                        job.config.schedule,
+                        job.config.task,
+                        next,
+                        last,
+                        tag
                     );
+                    if let Some(origin) = &job.config.origin {
+                        let session = sessions
+                            .as_ref()
+                            .and_then(|s| s.index().find_by_id(origin.session_id))
+                            .map(|entry| {
+                                format!(" ('{}', rustant resume {})", entry.name, entry.name)
+                            })
+                            .unwrap_or_default();
+                        println!(
+                            "      {} — created by the agent in session={}{}, approved {}",
+                            origin.interpretation,
+                            origin.session_id,
+                            session,
+                            origin.approved_at.format("%Y-%m-%d %H:%M UTC")
+                        );
+                        println!(
+                            "      runs as {} in {} mode, max {} iterations and ${:.2} per run",
+                            origin
+                                .grant
+                                .persona
+                                .as_deref()
+                                .unwrap_or("the default persona"),
+                            origin.grant.approval_mode,
+                            origin.budget.max_iterations,
+                            origin.budget.max_cost_usd
+                        );
+                        if let Some(reason) = &origin.paused {
+                            println!("      PAUSED until re-approved: {}", reason);
+                        }
+                    }
                 }
             }
             Ok(())
@@ -833,7 +1570,8 @@ async fn handle_cron(action: CronAction, workspace: &Path) -> anyhow::Result<()>
             task,
         } => {
             let mut scheduler = load_scheduler();
-            let job_config = rustant_core::CronJobConfig::new(&name, &schedule, &task);
+            let mut job_config = rustant_core::CronJobConfig::new(&name, &schedule, &task);
+            job_config.workspace = Some(workspace.to_path_buf());
             // Validate and add to scheduler
             scheduler.add_job(job_config)?;
             let job = scheduler.get_job(&name).unwrap();
@@ -854,6 +1592,11 @@ async fn handle_cron(action: CronAction, workspace: &Path) -> anyhow::Result<()>
                 Some(job) => {
                     println!("Manually triggering job '{}'...", name);
                     println!("  Task: {}", job.config.task);
+                    if let Some(period) = rustant_core::BriefingPeriod::from_task(&job.config.task)
+                    {
+                        println!();
+                        return handle_briefing(period, true, workspace).await;
+                    }
                     println!("  (Task execution requires an active agent session)");
                     Ok(())
                 }
@@ -880,9 +1623,64 @@ async fn handle_cron(action: CronAction, workspace: &Path) -> anyhow::Result<()>
             let mut scheduler = load_scheduler();
             scheduler.remove_job(&name)?;
             save_scheduler(&scheduler)?;
+            let mut history = load_history();
+            let remaining: Vec<&str> = scheduler
+                .list_jobs()
+                .iter()
+                .map(|j| j.config.name.as_str())
+                .collect();
+            if history.prune(scheduler_config.history_runs, remaining) > 0 {
+                history.save(&state_dir)?;
+            }
             println!("Cron job '{}' removed.", name);
             Ok(())
         }
+        CronAction::Kill { name, release } => {
+            let mut kill_switch = rustant_core::KillSwitch::load(workspace);
+            if release {
+                if kill_switch.release(&name) {
+                    kill_switch.save(workspace)?;
+                    println!("Kill switch for '{}' released; the job runs again.", name);
+                } else {
+                    println!("Cron job '{}' was not killed.", name);
+                }
+            } else if kill_switch.kill(&name) {
+                kill_switch.save(workspace)?;
+                println!(
+                    "Cron job '{}' killed; it will not run until released.",
+                    name
+                );
+                println!("  Release with: rustant cron kill {} --release", name);
+            } else {
+                println!("Cron job '{}' is already killed.", name);
+            }
+            Ok(())
+        }
+        CronAction::History { name, limit } => {
+            let history = load_history();
+            let runs = history.runs(&name);
+            if runs.is_empty() {
+                println!("No recorded runs of '{}'.", name);
+                return Ok(());
+            }
+            println!("Runs of '{}' (newest first):", name);
+            for run in runs.iter().take(limit) {
+                println!(
+                    "  {}  {:<10} {}s",
+                    run.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                    run.outcome.to_string(),
+                    run.duration().num_seconds()
+                );
+                if let Some(error) = &run.error {
+                    println!("      error: {}", error);
+                }
+                if let Some(output) = &run.output {
+                    let first = output.lines().next().unwrap_or_default();
+                    println!("      output: {}", first);
+                }
+            }
+            Ok(())
+        }
         CronAction::Jobs => {
             let manager = rustant_core::JobManager::new(scheduler_config.max_background_jobs);
             let jobs = manager.list();
@@ -914,12 +1712,11 @@ async fn handle_cron(action: CronAction, workspace: &Path) -> anyhow::Result<()>
     }
 }
 
-/// Load the Slack OAuth token from the keyring and create a RealSlackHttp client.
+/// Load the Slack OAuth token from the credential store and create a RealSlackHttp client.
 fn load_slack_client() -> anyhow::Result<rustant_core::channels::slack::RealSlackHttp> {
-    use rustant_core::credentials::KeyringCredentialStore;
     use rustant_core::oauth;
 
-    let store = KeyringCredentialStore::new();
+    let store = rustant_core::credentials::configured_store();
     let token = oauth::load_oauth_token(&store, "slack").map_err(|e| {
         anyhow::anyhow!(
             "No Slack OAuth token found. Run `rustant auth login slack` first.\n{}",
@@ -1557,16 +2354,84 @@ fn resolve_frontend_dir() -> Option<std::path::PathBuf> {
     None
 }
 
-async fn handle_ui(port: u16) -> anyhow::Result<()> {
-    use tower_http::services::{ServeDir, ServeFile};
+/// Runs dashboard-submitted tasks, one fresh agent per task.
+struct GatewayTaskRunner {
+    workspace: std::path::PathBuf,
+    /// The gateway's PTY sessions, for shell commands flagged interactive.
+    pty: std::sync::Arc<rustant_core::gateway::PtyManager>,
+}
 
-    println!("Starting Rustant Dashboard...");
-    println!();
+#[async_trait::async_trait]
+impl rustant_core::gateway::TaskRunner for GatewayTaskRunner {
+    async fn run(
+        &self,
+        request: rustant_core::gateway::TaskRequest,
+        progress: rustant_core::gateway::TaskProgress,
+        cancel: tokio_util::sync::CancellationToken,
+    ) -> Result<rustant_core::agent::TaskResult, String> {
+        // A task's workspace is a directory or the name of a registered one.
+        let workspace = match request.workspace.as_deref() {
+            Some(requested) if !Path::new(requested).is_dir() => load_workspace_registry()
+                .resolve(requested)
+                .unwrap_or_else(|_| std::path::PathBuf::from(requested)),
+            Some(requested) => std::path::PathBuf::from(requested),
+            None => self.workspace.clone(),
+        };
+        if !workspace.is_dir() {
+            return Err(format!(
+                "Workspace '{}' does not exist",
+                workspace.display()
+            ));
+        }
 
-    // Resolve frontend static assets
-    let frontend_dir = resolve_frontend_dir();
-    if let Some(ref dir) = frontend_dir {
-        println!("Frontend directory: {}", dir.display());
+        let mut config = rustant_core::config::load_config(Some(&workspace), None)
+            .map_err(|e| format!("Configuration error: {}", e))?;
+        if let Some(max) = request.options.max_iterations {
+            config.safety.max_iterations = max;
+        }
+        let provider = rustant_core::create_provider(&config.llm)
+            .map_err(|e| format!("LLM provider init failed: {}", e))?;
+        let form_tool = rustant_tools::canvas::WaitForFormTool::new(progress.clone());
+        let callback =
+            std::sync::Arc::new(rustant_core::gateway::TaskProgressCallback::new(progress));
+        let mut agent = rustant_core::Agent::new(provider, config, callback);
+        let mut registry = rustant_tools::registry::ToolRegistry::new();
+        rustant_tools::register_builtin_tools(&mut registry, workspace.clone());
+        let shell = rustant_tools::shell::ShellExecTool::new(workspace.clone())
+            .with_pty(std::sync::Arc::clone(&self.pty));
+        let _ = registry.unregister("shell_exec");
+        registry
+            .register(std::sync::Arc::new(shell))
+            .map_err(|e| e.to_string())?;
+        registry
+            .register(std::sync::Arc::new(form_tool))
+            .map_err(|e| e.to_string())?;
+        crate::repl::register_agent_tools_from_registry(&mut agent, &registry, &workspace);
+
+        let agent_cancel = agent.cancellation_token();
+        let watcher = tokio::spawn(async move {
+            cancel.cancelled().await;
+            agent_cancel.cancel();
+        });
+        let result = agent
+            .process_task(&request.text)
+            .await
+            .map_err(|e| e.to_string());
+        watcher.abort();
+        result
+    }
+}
+
+async fn handle_ui(port: u16, workspace: &Path) -> anyhow::Result<()> {
+    use tower_http::services::{ServeDir, ServeFile};
+
+    println!("Starting Rustant Dashboard...");
+    println!();
+
+    // Resolve frontend static assets
+    let frontend_dir = resolve_frontend_dir();
+    if let Some(ref dir) = frontend_dir {
+        println!("Frontend directory: {}", dir.display());
     } else {
         println!("Warning: Frontend directory not found.");
         println!("  Set RUSTANT_FRONTEND_DIR or run from the workspace root.");
@@ -1574,20 +2439,83 @@ async fn handle_ui(port: u16) -> anyhow::Result<()> {
         println!();
     }
 
-    // Start the gateway server in the background
-    let config = rustant_core::gateway::GatewayConfig {
-        enabled: true,
-        host: "127.0.0.1".into(),
-        port,
-        auth_tokens: Vec::new(),
-        max_connections: 50,
-        session_timeout_secs: 3600,
-        broadcast_capacity: 256,
+    // A focus block cut short by a crash must not leave Do Not Disturb on.
+    if let Some(notice) = rustant_tools::pomodoro::reconcile_focus(workspace).await {
+        println!("{}", notice);
+        println!();
+    }
+
+    // Start the gateway server in the background. [gateway] settings apply,
+    // except that the dashboard always binds to localhost on `port`.
+    let agent_config = rustant_core::config::load_config(Some(workspace), None).unwrap_or_default();
+    let config = match agent_config.gateway.clone() {
+        Some(gateway) => rustant_core::gateway::GatewayConfig {
+            enabled: true,
+            host: "127.0.0.1".into(),
+            port,
+            ..gateway
+        },
+        None => rustant_core::gateway::GatewayConfig {
+            enabled: true,
+            host: "127.0.0.1".into(),
+            port,
+            auth_tokens: Vec::new(),
+            task_tokens: Vec::new(),
+            max_connections: 50,
+            session_timeout_secs: 3600,
+            broadcast_capacity: 256,
+            event_queue_capacity: 256,
+            max_concurrent_tasks: 1,
+            max_queued_tasks: 16,
+            pty: Default::default(),
+            pairing: Default::default(),
+            ping_interval_secs: 30,
+        },
     };
 
     let gw: rustant_core::gateway::SharedGateway = std::sync::Arc::new(tokio::sync::Mutex::new(
         rustant_core::gateway::GatewayServer::new(config.clone()),
     ));
+    let mut phase = {
+        let mut gw = gw.lock().await;
+        let pty = gw.pty();
+        gw.set_task_runner(std::sync::Arc::new(GatewayTaskRunner {
+            workspace: workspace.to_path_buf(),
+            pty,
+        }));
+        gw.set_agent_config(&agent_config);
+        gw.set_approval_timeouts(
+            agent_config.safety.approval_mode,
+            agent_config.safety.approval_timeouts.clone(),
+        );
+        gw.set_approval_escalator(std::sync::Arc::new(
+            rustant_core::gateway::ChannelEscalator::new(
+                agent_config.channels.clone().unwrap_or_default(),
+            ),
+        ));
+        gw.set_default_workspace(workspace_label(workspace));
+        gw.set_cron_state_dir(workspace.join(".rustant").join("cron"));
+        gw.set_focus_workspace(workspace);
+        if agent_config.event_store.enabled
+            && let Some(path) = agent_config.event_store.resolved_path()
+        {
+            let cap = rustant_core::event_store::CostCap::from_config(&agent_config, workspace);
+            gw.set_cost_tracking(path, cap);
+        }
+        let config_workspace = workspace.to_path_buf();
+        gw.set_config_loader(Box::new(move || {
+            rustant_core::config::load_config(Some(&config_workspace), None)
+                .map_err(|e| e.to_string())
+        }));
+        gw.add_reload_handler(Box::new(LoggingReloadHandler));
+        if let Err(e) = gw.set_state_dir(&workspace.join(".rustant")) {
+            tracing::warn!("Paired devices unavailable: {}", e);
+        }
+        gw.shutdown_phase()
+    };
+
+    let clipboard_history = start_clipboard_history(&agent_config.clipboard, workspace);
+    let digest_delivery = start_digest_delivery(&agent_config);
 
     let gw_for_server = gw.clone();
 
@@ -1597,7 +2525,7 @@ async fn handle_ui(port: u16) -> anyhow::Result<()> {
     // Merge with static file serving if frontend is available
     let addr = format!("127.0.0.1:{}", port);
 
-    tokio::spawn(async move {
+    let server = tokio::spawn(async move {
         let listener = match tokio::net::TcpListener::bind(&addr).await {
             Ok(l) => l,
             Err(e) => {
@@ -1605,6 +2533,11 @@ async fn handle_ui(port: u16) -> anyhow::Result<()> {
                 return;
             }
         };
+        let closed = async move {
+            let _ = phase
+                .wait_for(|p| *p == rustant_core::gateway::ShutdownPhase::Closed)
+                .await;
+        };
 
         if let Some(dir) = frontend_dir {
             // Serve frontend files as fallback — "/" returns index.html
@@ -1612,12 +2545,24 @@ async fn handle_ui(port: u16) -> anyhow::Result<()> {
             let static_service = ServeDir::new(&dir).not_found_service(ServeFile::new(&index_file));
             let app = api_router.fallback_service(static_service);
 
-            if let Err(e) = axum::serve(listener, app).await {
+            if let Err(e) = axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .with_graceful_shutdown(closed)
+            .await
+            {
                 eprintln!("Gateway error: {}", e);
             }
         } else {
             // API-only mode (no frontend)
-            if let Err(e) = axum::serve(listener, api_router).await {
+            if let Err(e) = axum::serve(
+                listener,
+                api_router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .with_graceful_shutdown(closed)
+            .await
+            {
                 eprintln!("Gateway error: {}", e);
             }
         }
@@ -1638,11 +2583,413 @@ async fn handle_ui(port: u16) -> anyhow::Result<()> {
     println!("  http://127.0.0.1:{}/health", port);
     println!("  ws://127.0.0.1:{}/ws", port);
     println!();
-    println!("Press Ctrl+C to stop.");
+    println!("Press Ctrl+C to stop. Run `rustant gateway reload` to apply config changes.");
 
-    // Wait forever (or until Ctrl+C)
-    loop {
-        tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+    wait_for_shutdown_signal(&gw).await;
+    println!();
+    println!(
+        "Shutting down; waiting up to {}s for running tasks...",
+        UI_SHUTDOWN_GRACE.as_secs()
+    );
+    if let Some(sampler) = clipboard_history {
+        sampler.abort();
+    }
+    if let Some(runner) = digest_delivery {
+        runner.abort();
+    }
+    let cancelled = rustant_core::gateway::shutdown_gateway(gw, UI_SHUTDOWN_GRACE).await;
+    if cancelled > 0 {
+        println!("Cancelled {} task(s) still running.", cancelled);
+    }
+    let _ = server.await;
+    Ok(())
+}
+
+/// Start recording clipboard changes when `[clipboard] history` is on.
+/// Recording runs only while the dashboard does.
+fn start_clipboard_history(
+    config: &rustant_core::clipboard::ClipboardConfig,
+    workspace: &Path,
+) -> Option<tokio::task::JoinHandle<()>> {
+    use rustant_core::clipboard::{ClipboardHistory, ClipboardSampler, SystemClipboard};
+
+    if !config.history {
+        return None;
+    }
+    if !cfg!(target_os = "macos") {
+        println!("Clipboard history is only recorded on macOS.");
+        return None;
+    }
+    match ClipboardHistory::open(workspace, config) {
+        Ok(history) => {
+            println!("Recording clipboard history; use /clip in the REPL to attach an entry.");
+            let source = std::sync::Arc::new(SystemClipboard);
+            Some(ClipboardSampler::new(source, history, config.clone()).spawn())
+        }
+        Err(e) => {
+            println!("Clipboard history disabled: {}", e);
+            None
+        }
+    }
+}
+
+/// Start scheduled digest delivery for channels with `digest_delivery` set.
+/// Delivery runs only while the dashboard does.
+fn start_digest_delivery(
+    config: &rustant_core::AgentConfig,
+) -> Option<tokio::task::JoinHandle<()>> {
+    let runner = rustant_core::DigestRunner::from_config(
+        config.intelligence.as_ref()?,
+        &config.channels.clone().unwrap_or_default(),
+        chrono::Utc::now(),
+    )?;
+    let mut sources = runner.scheduler().scheduled_channels();
+    sources.sort_unstable();
+    println!("Delivering scheduled digests for: {}", sources.join(", "));
+    Some(runner.spawn())
+}
+
+/// How long `rustant ui` lets running tasks finish on shutdown.
+const UI_SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Applies `[logging]` changes to the stderr filter on gateway reload.
+struct LoggingReloadHandler;
+
+impl rustant_core::gateway::ReloadHandler for LoggingReloadHandler {
+    fn section(&self) -> &str {
+        "logging"
+    }
+
+    fn apply(&self, config: &rustant_core::AgentConfig) -> Result<(), String> {
+        crate::set_log_filter(config.logging.as_ref().and_then(|l| l.filter.as_deref()))
+    }
+}
+
+/// Wait for Ctrl+C or SIGTERM, reloading the gateway config on each SIGHUP.
+async fn wait_for_shutdown_signal(gw: &rustant_core::gateway::SharedGateway) {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        if let (Ok(mut term), Ok(mut hup)) = (
+            signal(SignalKind::terminate()),
+            signal(SignalKind::hangup()),
+        ) {
+            loop {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => return,
+                    _ = term.recv() => return,
+                    _ = hup.recv() => {
+                        let report = gw.lock().await.reload_from_source();
+                        rustant_core::gateway::dispatch_tasks(gw.clone()).await;
+                        println!("Configuration reloaded: {}", report.summary());
+                    }
+                }
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = gw;
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Query (or reload) the gateway started by `rustant ui` over its REST API.
+pub async fn handle_gateway(action: GatewayAction, workspace: &Path) -> anyhow::Result<()> {
+    let client = reqwest::Client::new();
+    // Device management needs a task token when the gateway has any tokens.
+    let operator_token = rustant_core::config::load_config(Some(workspace), None)
+        .ok()
+        .and_then(|c| c.gateway)
+        .and_then(|g| g.task_tokens.into_iter().next())
+        .unwrap_or_default();
+    match action {
+        GatewayAction::Status { port } => {
+            let status: serde_json::Value = client
+                .get(format!("http://127.0.0.1:{}/api/status", port))
+                .send()
+                .await
+                .map_err(|e| anyhow::anyhow!("Gateway not reachable on port {}: {}", port, e))?
+                .json()
+                .await?;
+            println!(
+                "Gateway v{} — up {}s, {} connection(s){}",
+                status["version"].as_str().unwrap_or("?"),
+                status["uptime_secs"].as_u64().unwrap_or(0),
+                status["active_connections"].as_u64().unwrap_or(0),
+                if status["shutting_down"].as_bool().unwrap_or(false) {
+                    ", shutting down"
+                } else {
+                    ""
+                }
+            );
+            if let Some(default) = status["default_workspace"].as_str() {
+                println!("Default workspace: {}", default);
+            }
+            for entry in status["workspaces"].as_array().into_iter().flatten() {
+                println!(
+                    "  {} — {} active session(s)",
+                    entry["name"].as_str().unwrap_or("?"),
+                    entry["sessions"].as_u64().unwrap_or(0)
+                );
+            }
+            match serde_json::from_value::<rustant_core::gateway::ReloadReport>(
+                status["last_reload"].clone(),
+            ) {
+                Ok(report) => print_reload_report(&report),
+                Err(_) => println!("Config: no reload since startup"),
+            }
+        }
+        GatewayAction::Reload { port } => {
+            let report: rustant_core::gateway::ReloadReport = client
+                .post(format!("http://127.0.0.1:{}/api/reload", port))
+                .send()
+                .await
+                .map_err(|e| anyhow::anyhow!("Gateway not reachable on port {}: {}", port, e))?
+                .json()
+                .await?;
+            print_reload_report(&report);
+            if !report.errors.is_empty() {
+                anyhow::bail!("Reload finished with errors");
+            }
+        }
+        GatewayAction::Pair { scope, png, port } => {
+            let scope: rustant_core::gateway::AuthScope =
+                scope.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+            let resp = client
+                .post(format!("http://127.0.0.1:{}/api/pairing", port))
+                .bearer_auth(&operator_token)
+                .json(&serde_json::json!({ "scope": scope }))
+                .send()
+                .await
+                .map_err(|e| anyhow::anyhow!("Gateway not reachable on port {}: {}", port, e))?;
+            let body = gateway_json(resp).await?;
+            let offer: rustant_core::gateway::PairingOffer =
+                serde_json::from_value(body["offer"].clone())?;
+            println!("{}", rustant_core::gateway::qr_terminal(&offer.url)?);
+            println!("Scan with the device to pair it ({} scope).", offer.scope);
+            println!("  URL:     {}", offer.url);
+            println!(
+                "  Expires: {} (single use)",
+                offer
+                    .challenge
+                    .expires_at
+                    .with_timezone(&chrono::Local)
+                    .format("%H:%M:%S")
+            );
+            if offer.url.starts_with("http://127.0.0.1")
+                || offer.url.starts_with("http://localhost")
+            {
+                println!(
+                    "  Note: phones cannot reach localhost; set [gateway.pairing] public_url."
+                );
+            }
+            if let Some(path) = png {
+                std::fs::write(&path, rustant_core::gateway::qr_png(&offer.url, 8)?)?;
+                println!("  QR image written to {}", path.display());
+            }
+        }
+        GatewayAction::Devices { port } => {
+            let resp = client
+                .get(format!("http://127.0.0.1:{}/api/devices", port))
+                .bearer_auth(&operator_token)
+                .send()
+                .await
+                .map_err(|e| anyhow::anyhow!("Gateway not reachable on port {}: {}", port, e))?;
+            let body = gateway_json(resp).await?;
+            let devices: Vec<rustant_core::gateway::PairedDevice> =
+                serde_json::from_value(body["devices"].clone())?;
+            if devices.is_empty() {
+                println!("No paired devices. Pair one with `rustant daemon pair`.");
+            }
+            for device in devices {
+                println!(
+                    "{}  {:<24} {:<8} {:<9} last seen {}",
+                    device.identity.device_id,
+                    device.identity.device_name,
+                    device.platform,
+                    device.scope,
+                    device
+                        .identity
+                        .last_seen
+                        .with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M")
+                );
+            }
+        }
+        GatewayAction::Revoke { device, port } => {
+            let device_id = match uuid::Uuid::parse_str(&device) {
+                Ok(id) => id,
+                Err(_) => {
+                    // Look the device up by name.
+                    let resp = client
+                        .get(format!("http://127.0.0.1:{}/api/devices", port))
+                        .bearer_auth(&operator_token)
+                        .send()
+                        .await
+                        .map_err(|e| {
+                            anyhow::anyhow!("Gateway not reachable on port {}: {}", port, e)
+                        })?;
+                    let body = gateway_json(resp).await?;
+                    let devices: Vec<rustant_core::gateway::PairedDevice> =
+                        serde_json::from_value(body["devices"].clone())?;
+                    let matches: Vec<_> = devices
+                        .iter()
+                        .filter(|d| d.identity.device_name.eq_ignore_ascii_case(&device))
+                        .collect();
+                    match matches.as_slice() {
+                        [one] => one.identity.device_id,
+                        [] => anyhow::bail!("No paired device named '{}'", device),
+                        _ => anyhow::bail!(
+                            "Several devices are named '{}'; revoke by ID instead",
+                            device
+                        ),
+                    }
+                }
+            };
+            let resp = client
+                .delete(format!(
+                    "http://127.0.0.1:{}/api/devices/{}",
+                    port, device_id
+                ))
+                .bearer_auth(&operator_token)
+                .send()
+                .await
+                .map_err(|e| anyhow::anyhow!("Gateway not reachable on port {}: {}", port, e))?;
+            let body = gateway_json(resp).await?;
+            println!(
+                "Revoked {} ({}); its connections were closed.",
+                body["revoked"]["device_name"].as_str().unwrap_or("device"),
+                device_id
+            );
+        }
+        GatewayAction::Clients { port } => {
+            let (clients, body) = fetch_gateway_clients(&client, port, &operator_token).await?;
+            if clients.is_empty() {
+                println!("No clients connected.");
+            }
+            let now = chrono::Utc::now();
+            for c in &clients {
+                println!(
+                    "{}  {:<32} {:<21} {:<18} {:<6} connected {}, active {}s ago, {} in / {} out",
+                    c.connection_id,
+                    c.label(),
+                    c.remote_addr.map_or("-".to_string(), |a| a.to_string()),
+                    c.token_id.as_deref().unwrap_or("unauthenticated"),
+                    if c.authenticated {
+                        c.scope.to_string()
+                    } else {
+                        "-".to_string()
+                    },
+                    c.connected_at
+                        .with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M"),
+                    (now - c.last_activity).num_seconds().max(0),
+                    c.messages_received,
+                    c.messages_sent
+                );
+            }
+            if let Ok(counts) = serde_json::from_value::<rustant_core::gateway::DisconnectCounts>(
+                body["disconnects"].clone(),
+            ) {
+                println!(
+                    "Disconnects since start: {} closed, {} stale, {} by operator",
+                    counts.closed, counts.stale, counts.kicked
+                );
+            }
+        }
+        GatewayAction::Disconnect {
+            client: wanted,
+            port,
+        } => {
+            let (clients, _) = fetch_gateway_clients(&client, port, &operator_token).await?;
+            let matches: Vec<_> = clients
+                .iter()
+                .filter(|c| {
+                    c.connection_id
+                        .to_string()
+                        .starts_with(&wanted.to_lowercase())
+                        || c.client_name
+                            .as_deref()
+                            .is_some_and(|n| n.eq_ignore_ascii_case(&wanted))
+                })
+                .collect();
+            let target = match matches.as_slice() {
+                [one] => *one,
+                [] => anyhow::bail!("No connected client matches '{}'", wanted),
+                _ => anyhow::bail!(
+                    "Several clients match '{}'; use more of the connection ID",
+                    wanted
+                ),
+            };
+            let resp = client
+                .delete(format!(
+                    "http://127.0.0.1:{}/api/clients/{}",
+                    port, target.connection_id
+                ))
+                .bearer_auth(&operator_token)
+                .send()
+                .await
+                .map_err(|e| anyhow::anyhow!("Gateway not reachable on port {}: {}", port, e))?;
+            gateway_json(resp).await?;
+            println!(
+                "Disconnected {} ({}).",
+                target.label(),
+                target.connection_id
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Fetch the gateway's connected clients and the raw response body.
+async fn fetch_gateway_clients(
+    client: &reqwest::Client,
+    port: u16,
+    operator_token: &str,
+) -> anyhow::Result<(
+    Vec<rustant_core::gateway::ConnectionInfo>,
+    serde_json::Value,
+)> {
+    let resp = client
+        .get(format!("http://127.0.0.1:{}/api/clients", port))
+        .bearer_auth(operator_token)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("Gateway not reachable on port {}: {}", port, e))?;
+    let body = gateway_json(resp).await?;
+    let clients = serde_json::from_value(body["clients"].clone())?;
+    Ok((clients, body))
+}
+
+/// Decode a gateway REST response, turning error responses into errors.
+async fn gateway_json(resp: reqwest::Response) -> anyhow::Result<serde_json::Value> {
+    let status = resp.status();
+    let body: serde_json::Value = resp.json().await?;
+    if !status.is_success() {
+        anyhow::bail!(
+            "Gateway refused the request ({}): {}",
+            status,
+            body["error"].as_str().unwrap_or("unknown error")
+        );
+    }
+    Ok(body)
+}
+
+fn print_reload_report(report: &rustant_core::gateway::ReloadReport) {
+    println!(
+        "Config reloaded at {}",
+        report.requested_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    let list = |items: &[String]| {
+        if items.is_empty() {
+            "none".to_string()
+        } else {
+            items.join(", ")
+        }
+    };
+    println!("  Applied:  {}", list(&report.applied));
+    println!("  Deferred: {} (restart required)", list(&report.deferred));
+    for error in &report.errors {
+        println!("  Error:    {}", error);
     }
 }
 
@@ -1670,7 +3017,7 @@ pub async fn handle_canvas(action: CanvasAction) -> anyhow::Result<()> {
                 ContentType::Chart => {
                     let spec: rustant_core::canvas::ChartSpec = serde_json::from_str(&content)
                         .map_err(|e| anyhow::anyhow!("Invalid chart JSON: {}", e))?;
-                    let config = rustant_core::canvas::render_chart_config(&spec);
+                    let config = rustant_core::canvas::render_chart_config(&spec)?;
                     println!("Chart.js config:\n{}", config);
                 }
                 ContentType::Table => {
@@ -1731,25 +3078,34 @@ pub async fn handle_canvas(action: CanvasAction) -> anyhow::Result<()> {
     }
 }
 
-pub async fn handle_skill(action: SkillAction) -> anyhow::Result<()> {
-    use rustant_core::skills::{SkillLoader, parse_skill_md, validate_skill};
+pub async fn handle_skill(action: SkillAction, workspace: &Path) -> anyhow::Result<()> {
+    use rustant_core::skills::install::{self, SkillLock, UpdateStatus};
+    use rustant_core::skills::{SkillLoader, SkillSource, parse_skill_md, validate_skill};
 
-    match action {
-        SkillAction::List { dir } => {
-            let skills_dir = dir.unwrap_or_else(|| {
-                directories::ProjectDirs::from("dev", "rustant", "rustant")
-                    .map(|d| d.data_dir().join("skills").to_string_lossy().into_owned())
-                    .unwrap_or_else(|| ".rustant/skills".into())
-            });
+    let config = rustant_core::config::load_config(Some(workspace), None).unwrap_or_default();
+    let skills = config.skills.clone().unwrap_or_default();
+    let skills_dir = |dir: Option<String>| {
+        dir.map(std::path::PathBuf::from)
+            .unwrap_or_else(|| skills.skills_dir())
+    };
 
+    match action {
+        SkillAction::List { dir, offline } => {
+            let skills_dir = skills_dir(dir);
             let loader = SkillLoader::new(&skills_dir);
             let results = loader.scan();
+            let lock = SkillLock::load(&skills_dir).unwrap_or_else(|e| {
+                eprintln!("Warning: {}", e);
+                SkillLock::default()
+            });
 
             if results.is_empty() {
-                println!("No skill files found in: {}", skills_dir);
-                println!("Create SKILL.md files in that directory to define skills.");
+                println!("No skill files found in: {}", skills_dir.display());
+                println!(
+                    "Create SKILL.md files in that directory, or run `rustant skill install <source>`."
+                );
             } else {
-                println!("Skills in {}:", skills_dir);
+                println!("Skills in {}:", skills_dir.display());
                 for result in &results {
                     match result {
                         Ok(skill) => {
@@ -1760,6 +3116,32 @@ pub async fn handle_skill(action: SkillAction) -> anyhow::Result<()> {
                                 skill.description,
                                 skill.tools.len()
                             );
+                            let Some(locked) = lock.skills.get(&skill.name) else {
+                                println!("    origin: local file");
+                                continue;
+                            };
+                            let provenance = &locked.provenance;
+                            let commit = provenance
+                                .commit
+                                .as_deref()
+                                .map(|c| format!(" @ {}", &c[..c.len().min(12)]))
+                                .unwrap_or_default();
+                            println!(
+                                "    origin: {}{} ({})",
+                                provenance.source, commit, provenance.signature
+                            );
+                            if !offline {
+                                match install::check_for_update(locked).await {
+                                    UpdateStatus::UpToDate => println!("    up to date"),
+                                    UpdateStatus::Available(_) => println!(
+                                        "    update available (rustant skill update {})",
+                                        skill.name
+                                    ),
+                                    UpdateStatus::Unknown(reason) => {
+                                        println!("    update check failed: {}", reason)
+                                    }
+                                }
+                            }
                         }
                         Err((path, err)) => {
                             println!("  {} (error: {})", path.display(), err);
@@ -1770,6 +3152,121 @@ pub async fn handle_skill(action: SkillAction) -> anyhow::Result<()> {
             }
             Ok(())
         }
+        SkillAction::Install { source, yes, dir } => {
+            let skills_dir = skills_dir(dir);
+            let source = SkillSource::parse(&source)?;
+            println!("Fetching skill from {}...", source);
+            let fetched = install::fetch(&source, &skills.trusted_keys).await?;
+            let review = review_skill(&fetched.skill, workspace);
+            print_skill_summary(&fetched, &review);
+            if !review.blocking.is_empty() {
+                anyhow::bail!(
+                    "Refusing to install '{}': {}",
+                    fetched.skill.name,
+                    review.blocking.join("; ")
+                );
+            }
+
+            let lock = SkillLock::load(&skills_dir)?;
+            let installed = lock.skills.get(&fetched.skill.name);
+            if let Some(installed) = installed {
+                println!(
+                    "\nReplaces installed v{} from {}.",
+                    installed.version, installed.provenance.source
+                );
+            }
+            let extra = install::extra_confirmation(
+                &fetched.provenance,
+                &review,
+                config.safety.approval_mode,
+                installed.map(|l| &l.provenance),
+            );
+            let prompt = format!("Install skill '{}'?", fetched.skill.name);
+            if !confirm_skill(&prompt, yes, extra, &fetched.skill.name)? {
+                println!("Install cancelled.");
+                return Ok(());
+            }
+            let path = install::install(&skills_dir, &fetched)?;
+            println!("Installed '{}' to {}", fetched.skill.name, path.display());
+            Ok(())
+        }
+        SkillAction::Update { name, yes, dir } => {
+            let skills_dir = skills_dir(dir);
+            let lock = SkillLock::load(&skills_dir)?;
+            let names: Vec<String> = match name {
+                Some(name) => vec![name],
+                None => lock.skills.keys().cloned().collect(),
+            };
+            if names.is_empty() {
+                println!(
+                    "No skills installed with `rustant skill install` in {}.",
+                    skills_dir.display()
+                );
+                return Ok(());
+            }
+
+            for name in names {
+                let locked = lock
+                    .skills
+                    .get(&name)
+                    .ok_or_else(|| install::InstallError::NotInstalled(name.clone()))?;
+                let source = &locked.provenance.source;
+                let fetched = match install::fetch(source, &skills.trusted_keys).await {
+                    Ok(fetched) => fetched,
+                    Err(e) => {
+                        println!("{}: {}", name, e);
+                        continue;
+                    }
+                };
+                if fetched.provenance.sha256 == locked.provenance.sha256 {
+                    println!("{}: up to date", name);
+                    continue;
+                }
+
+                println!("{}: changes from {}", name, source);
+                let current =
+                    std::fs::read_to_string(skills_dir.join(&locked.file)).unwrap_or_default();
+                if let Ok(old) = parse_skill_md(&current) {
+                    for change in install::describe_changes(&old, &fetched.skill) {
+                        println!("  {}", change);
+                    }
+                }
+                if fetched.skill.name != name {
+                    println!(
+                        "  skill renamed to '{}'; it will be installed alongside '{}'",
+                        fetched.skill.name, name
+                    );
+                }
+                let diff = similar::TextDiff::from_lines(&current, &fetched.content);
+                println!(
+                    "\n{}",
+                    diff.unified_diff()
+                        .context_radius(3)
+                        .header("installed", "source")
+                );
+
+                let review = review_skill(&fetched.skill, workspace);
+                print_skill_summary(&fetched, &review);
+                if !review.blocking.is_empty() {
+                    println!("Not updating '{}': {}", name, review.blocking.join("; "));
+                    continue;
+                }
+                let extra = install::extra_confirmation(
+                    &fetched.provenance,
+                    &review,
+                    config.safety.approval_mode,
+                    Some(&locked.provenance),
+                );
+                let prompt = format!("Update skill '{}'?", name);
+                if !confirm_skill(&prompt, yes, extra, &fetched.skill.name)? {
+                    println!("Skipped '{}'.", name);
+                    continue;
+                }
+                install::install(&skills_dir, &fetched)?;
+                println!("Updated '{}' to v{}.", name, fetched.skill.version);
+            }
+            Ok(())
+        }
         SkillAction::Info { path } => {
             let content = std::fs::read_to_string(&path)
                 .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", path, e))?;
@@ -1853,6 +3350,96 @@ pub async fn handle_skill(action: SkillAction) -> anyhow::Result<()> {
     }
 }
 
+/// Review a fetched skill against this workspace's tools and environment secrets.
+fn review_skill(
+    skill: &rustant_core::skills::SkillDefinition,
+    workspace: &Path,
+) -> rustant_core::skills::SkillReview {
+    let mut registry = rustant_tools::registry::ToolRegistry::new();
+    rustant_tools::register_builtin_tools(&mut registry, workspace.to_path_buf());
+    let secrets: Vec<String> = skill
+        .requires
+        .iter()
+        .filter(|r| r.req_type == "secret" && std::env::var_os(&r.name).is_some())
+        .map(|r| r.name.clone())
+        .collect();
+    rustant_core::skills::install::review(skill, &registry.list_names(), &secrets)
+}
+
+fn print_skill_summary(
+    fetched: &rustant_core::skills::FetchedSkill,
+    review: &rustant_core::skills::SkillReview,
+) {
+    let skill = &fetched.skill;
+    let provenance = &fetched.provenance;
+    println!(
+        "\nSkill: {} v{} - {}",
+        skill.name, skill.version, skill.description
+    );
+    if let Some(author) = &skill.author {
+        println!("Author: {}", author);
+    }
+    println!("Risk Level: {:?}", review.risk_level);
+    if !skill.tools.is_empty() {
+        println!("Tools:");
+        for tool in &skill.tools {
+            println!("  {} - {}", tool.name, tool.description);
+        }
+    }
+    if !skill.requires.is_empty() {
+        println!("Requires:");
+        for req in &skill.requires {
+            println!("  {} ({})", req.name, req.req_type);
+        }
+    }
+    println!("Provenance:");
+    println!("  source: {}", provenance.source);
+    if let Some(commit) = &provenance.commit {
+        println!("  commit: {}", commit);
+    }
+    println!("  sha256: {}", provenance.sha256);
+    println!("  signature: {}", provenance.signature);
+    for (label, items) in [
+        ("Warnings", &review.warnings),
+        ("Suspicious", &review.suspicious),
+        ("Blocking", &review.blocking),
+    ] {
+        if !items.is_empty() {
+            println!("{}:", label);
+            for item in items {
+                println!("  - {}", item);
+            }
+        }
+    }
+}
+
+/// Ask before installing a skill. `--yes` skips the first prompt; a
+/// required second confirmation always asks for the skill's name.
+fn confirm_skill(
+    prompt: &str,
+    yes: bool,
+    extra: Option<String>,
+    name: &str,
+) -> anyhow::Result<bool> {
+    if !yes
+        && !dialoguer::Confirm::new()
+            .with_prompt(prompt)
+            .default(false)
+            .interact()?
+    {
+        return Ok(false);
+    }
+    let Some(reason) = extra else {
+        return Ok(true);
+    };
+    println!("\x1b[33mExtra confirmation required: {}\x1b[0m", reason);
+    let typed: String = dialoguer::Input::new()
+        .with_prompt(format!("Type '{}' to install anyway", name))
+        .allow_empty(true)
+        .interact_text()?;
+    Ok(typed.trim() == name)
+}
+
 pub async fn handle_plugin(action: PluginAction) -> anyhow::Result<()> {
     use rustant_plugins::NativePluginLoader;
 
@@ -1889,15 +3476,39 @@ pub async fn handle_plugin(action: PluginAction) -> anyhow::Result<()> {
     }
 }
 
-pub async fn handle_update(action: UpdateAction) -> anyhow::Result<()> {
-    use rustant_core::updater::{CURRENT_VERSION, UpdateChecker, UpdateConfig, Updater};
+pub async fn handle_update(action: UpdateAction, workspace: &Path) -> anyhow::Result<()> {
+    use rustant_core::updater::{
+        CURRENT_VERSION, InstallOutcome, ReleaseChannel, UpdateChecker, UpdateConfig, Updater,
+    };
+
+    let configured = rustant_core::config::load_config(Some(workspace), None)
+        .ok()
+        .and_then(|c| c.update)
+        .unwrap_or_default();
+    let with_channel = |channel: Option<String>| -> anyhow::Result<UpdateConfig> {
+        let mut config = configured.clone();
+        if let Some(name) = channel {
+            let parsed = ReleaseChannel::parse(&name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown channel '{}' (expected stable, beta, or nightly)",
+                    name
+                )
+            })?;
+            config.channel = parsed.as_str().to_string();
+        }
+        Ok(config)
+    };
 
     match action {
-        UpdateAction::Check => {
-            println!("Current version: {}", CURRENT_VERSION);
+        UpdateAction::Check { channel } => {
+            let config = with_channel(channel)?;
+            println!(
+                "Current version: {} (channel: {})",
+                CURRENT_VERSION,
+                config.release_channel()
+            );
             println!("Checking for updates...");
 
-            let config = UpdateConfig::default();
             let checker = UpdateChecker::new(config);
 
             match checker.check().await {
@@ -1934,14 +3545,24 @@ pub async fn handle_update(action: UpdateAction) -> anyhow::Result<()> {
             }
             Ok(())
         }
-        UpdateAction::Install => {
-            println!("Current version: {}", CURRENT_VERSION);
-            println!("Downloading and installing latest version...");
+        UpdateAction::Install { channel } => {
+            let config = with_channel(channel)?;
+            println!(
+                "Current version: {} (channel: {})",
+                CURRENT_VERSION,
+                config.release_channel()
+            );
+            println!("Downloading and verifying latest version...");
 
-            match Updater::update() {
-                Ok(()) => {
-                    println!("Update installed successfully!");
+            match Updater::install(&config).await {
+                Ok(InstallOutcome::UpToDate { version }) => {
+                    println!("Already up to date ({}).", version);
+                }
+                Ok(InstallOutcome::Installed { from, to, backup }) => {
+                    println!("Updated {} -> {} (signature verified).", from, to);
+                    println!("Previous binary kept at {}", backup.display());
                     println!("Restart rustant to use the new version.");
+                    println!("Run `rustant update rollback` to undo.");
                 }
                 Err(e) => {
                     println!("Update failed: {}", e);
@@ -1951,31 +3572,827 @@ pub async fn handle_update(action: UpdateAction) -> anyhow::Result<()> {
             }
             Ok(())
         }
+        UpdateAction::Rollback => {
+            match Updater::rollback() {
+                Ok(version) => {
+                    println!("Rolled back to {}.", version);
+                    println!("Restart rustant to use it.");
+                }
+                Err(e) => println!("Rollback failed: {}", e),
+            }
+            Ok(())
+        }
     }
 }
 
-/// Connect to configured external MCP servers and log results.
-///
-/// For each server with `auto_connect: true`, spawns the process, performs
-/// the MCP initialize handshake, and lists available tools. Logs warnings
-/// for servers that fail to connect.
-pub async fn connect_mcp_servers(
-    configs: &[rustant_core::ExternalMcpServerConfig],
-) -> Vec<(String, Vec<String>)> {
-    let mut connected = Vec::new();
-
-    for config in configs {
-        if !config.auto_connect {
-            continue;
-        }
-
-        tracing::info!(name = %config.name, command = %config.command, "Connecting to MCP server");
+/// Generate a briefing, print it, and optionally deliver it as configured.
+async fn handle_briefing(
+    period: rustant_core::BriefingPeriod,
+    deliver: bool,
+    workspace: &Path,
+) -> anyhow::Result<()> {
+    use rustant_core::briefing::BriefingDelivered;
 
-        match rustant_mcp::transport::ProcessTransport::spawn(
-            &config.command,
-            &config.args,
-            &config.env,
-        )
+    let config = rustant_core::config::load_config(Some(workspace), None)
+        .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+    let briefing_config = config.briefing.clone().unwrap_or_default();
+    let engine = rustant_tools::briefing::briefing_engine(workspace.to_path_buf(), &config);
+    // Without a usable provider, oversized sections are truncated instead of summarized.
+    let provider = rustant_core::create_provider(&config.llm).ok();
+    let briefing = engine
+        .generate(&briefing_config, period, chrono::Utc::now(), provider)
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    print!("{}", briefing.to_markdown());
+    if !deliver {
+        return Ok(());
+    }
+
+    let mut manager =
+        rustant_core::channels::build_channel_manager(&config.channels.unwrap_or_default());
+    let mut canvas = rustant_core::canvas::CanvasManager::new();
+    match briefing
+        .deliver(&briefing_config.delivery, &mut manager, &mut canvas)
+        .map_err(|e| anyhow::anyhow!("{}", e))?
+    {
+        BriefingDelivered::Nowhere => {
+            println!("\nNo [briefing.delivery] configured; briefing printed only.");
+        }
+        BriefingDelivered::Canvas { id, target } => {
+            println!("\nBriefing pushed to canvas '{}' (id: {}).", target, id);
+        }
+        BriefingDelivered::Queued { channel } => {
+            for (name, result) in manager.connect_all().await {
+                if let (true, Err(e)) = (name == channel, result) {
+                    anyhow::bail!("Failed to connect channel '{}': {}", channel, e);
+                }
+            }
+            let results = manager.flush_outgoing().await;
+            manager.disconnect_all().await;
+            for (out, result) in results {
+                result.map_err(|e| {
+                    anyhow::anyhow!("Failed to send briefing to '{}': {}", out.channel_name, e)
+                })?;
+            }
+            println!("\nBriefing sent to channel '{}'.", channel);
+        }
+    }
+    Ok(())
+}
+
+/// Named workspaces, or none if the registry cannot be read.
+pub(crate) fn load_workspace_registry() -> rustant_core::WorkspaceRegistry {
+    rustant_core::workspaces::default_workspaces_path()
+        .and_then(|path| rustant_core::WorkspaceRegistry::load(&path).ok())
+        .unwrap_or_default()
+}
+
+/// Name of the workspace at `path`, or the path when it has none.
+pub(crate) fn workspace_label(path: &Path) -> String {
+    rustant_core::workspaces::workspace_label(&load_workspace_registry(), path)
+}
+
+/// `rustant index status`: what indexing leaves out and why, or where one
+/// path stands.
+fn handle_index(action: IndexAction, workspace: &Path) -> anyhow::Result<()> {
+    use rustant_core::indexer::{IndexerConfig, explain_path, survey_workspace};
+
+    let config = rustant_core::config::load_config(Some(workspace), None)
+        .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+    let indexer_config = IndexerConfig::from(&config.index);
+    match action {
+        IndexAction::Status { path: Some(path) } => {
+            let status = explain_path(workspace, &indexer_config, &path);
+            println!("{}: {}", path.display(), status);
+        }
+        IndexAction::Status { path: None } => {
+            let (files, report) = survey_workspace(workspace, &indexer_config);
+            println!("Index status for {}", workspace.display());
+            println!("  Files indexed: {}", files.len());
+            println!("  Ignore rules: .gitignore (in git repositories)");
+            if report.ignore_files.is_empty() {
+                println!("    .rustantignore: none");
+            } else {
+                for file in &report.ignore_files {
+                    println!("    {}", file);
+                }
+            }
+            if !report.ignore_patterns.is_empty() {
+                println!("    [index] ignore: {}", report.ignore_patterns.join(", "));
+            }
+            let limit = |n: u64| {
+                if n == 0 {
+                    "no limit".to_string()
+                } else {
+                    n.to_string()
+                }
+            };
+            println!(
+                "  Budget per directory: {} files, {} KB",
+                limit(indexer_config.max_files_per_dir as u64),
+                limit(indexer_config.max_bytes_per_dir / 1024)
+            );
+            if !report.truncated.is_empty() {
+                println!("  Truncated directories ({}):", report.truncated.len());
+                for truncation in &report.truncated {
+                    println!("    {}", truncation);
+                }
+            }
+            if !report.too_large.is_empty() {
+                println!(
+                    "  Over the {} KB file limit ({}):",
+                    indexer_config.max_file_size / 1024,
+                    report.too_large.len()
+                );
+                for file in report.too_large.iter().take(20) {
+                    println!("    {}", file);
+                }
+                if report.too_large.len() > 20 {
+                    println!("    ... and {} more", report.too_large.len() - 20);
+                }
+            }
+            println!("  Unsupported file types: {}", report.unsupported);
+            if report.reached_file_limit {
+                println!(
+                    "  Stopped at the {}-file limit; later files are not indexed.",
+                    indexer_config.max_files
+                );
+            }
+            println!("Use `rustant index status --path <file>` to check a single path.");
+        }
+    }
+    Ok(())
+}
+
+/// `rustant stats`: reports over the local event store, or a read-only query.
+fn handle_stats(
+    report: Option<StatsReport>,
+    days: u32,
+    sql: Option<&str>,
+    workspace: &Path,
+) -> anyhow::Result<()> {
+    use rustant_core::event_store::{EventStore, format_cost};
+
+    let config = rustant_core::config::load_config(Some(workspace), None)
+        .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+    let path = config
+        .event_store
+        .resolved_path()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the rustant data directory"))?;
+    let store = EventStore::open_read_only(&path)?;
+
+    if let Some(sql) = sql {
+        let result = store.query(sql)?;
+        println!("{}", result.columns.join("\t"));
+        for row in &result.rows {
+            println!("{}", row.join("\t"));
+        }
+        if result.truncated {
+            eprintln!("(output truncated to {} rows)", result.rows.len());
+        }
+        return Ok(());
+    }
+
+    let since = chrono::Utc::now() - chrono::Duration::days(days as i64);
+    let reports = match report {
+        Some(report) => vec![report],
+        None => vec![
+            StatsReport::Tools { tool: None },
+            StatsReport::Cost {
+                month: None,
+                chart: false,
+            },
+            StatsReport::Workspaces,
+            StatsReport::Approvals,
+        ],
+    };
+    for (i, report) in reports.into_iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        match report {
+            StatsReport::Tools { tool: Some(tool) } => {
+                let failures = store.tool_failures(&tool, since, 20)?;
+                println!("Failures of {} in the last {} day(s):", tool, days);
+                if failures.is_empty() {
+                    println!("  none");
+                }
+                for failure in failures {
+                    println!(
+                        "  {:>5}  {:<18} {}  (last {})",
+                        failure.failures,
+                        failure.error_kind.as_deref().unwrap_or("-"),
+                        if failure.detail.is_empty() {
+                            "-"
+                        } else {
+                            failure.detail.as_str()
+                        },
+                        failure.last_seen
+                    );
+                }
+            }
+            StatsReport::Tools { tool: None } => {
+                println!("Tools, last {} day(s):", days);
+                println!(
+                    "  {:<24} {:>7} {:>8} {:>7} {:>9}",
+                    "TOOL", "CALLS", "FAILED", "RATE", "AVG MS"
+                );
+                for stats in store.tool_stats(since)? {
+                    println!(
+                        "  {:<24} {:>7} {:>8} {:>6.1}% {:>9.0}",
+                        stats.tool,
+                        stats.calls,
+                        stats.failures,
+                        stats.failure_rate() * 100.0,
+                        stats.avg_duration_ms
+                    );
+                }
+            }
+            StatsReport::Cost {
+                month: Some(month),
+                chart,
+            } => {
+                print_month_cost(&store, &month, chart, &config, workspace)?;
+            }
+            StatsReport::Cost { month: None, .. } => {
+                println!("LLM cost, last {} day(s):", days);
+                println!(
+                    "  {:<10} {:<28} {:>8} {:>11} {:>11} {:>10}",
+                    "DAY", "MODEL", "REQUESTS", "INPUT", "OUTPUT", "COST"
+                );
+                let (mut total, mut requests, mut unpriced) = (0.0, 0, 0);
+                for cost in store.cost_by_day(since)? {
+                    total += cost.cost_usd;
+                    requests += cost.requests;
+                    unpriced += cost.unpriced_requests;
+                    println!(
+                        "  {:<10} {:<28} {:>8} {:>11} {:>11} {:>10}",
+                        cost.day,
+                        cost.model,
+                        cost.requests,
+                        cost.input_tokens,
+                        cost.output_tokens,
+                        format_cost(cost.cost_usd, cost.requests, cost.unpriced_requests)
+                    );
+                }
+                println!("  Total: {}", format_cost(total, requests, unpriced));
+            }
+            StatsReport::Workspaces => {
+                println!("Busiest workspaces, last {} day(s):", days);
+                println!("  {:>6} {:>7} {:>9}  WORKSPACE", "TASKS", "TOOLS", "COST");
+                for activity in store.busiest_workspaces(since, 10)? {
+                    println!(
+                        "  {:>6} {:>7} ${:>8.4}  {}",
+                        activity.tasks, activity.tool_calls, activity.cost_usd, activity.workspace
+                    );
+                }
+            }
+            StatsReport::Approvals => {
+                println!("Approvals, last {} day(s):", days);
+                println!(
+                    "  {:<24} {:>9} {:>7} {:>7}",
+                    "TOOL", "APPROVED", "DENIED", "DENY"
+                );
+                let (mut approved, mut denied) = (0, 0);
+                for stats in store.approval_stats(since)? {
+                    approved += stats.approved;
+                    denied += stats.denied;
+                    println!(
+                        "  {:<24} {:>9} {:>7} {:>6.1}%",
+                        stats.tool,
+                        stats.approved,
+                        stats.denied,
+                        stats.deny_rate() * 100.0
+                    );
+                }
+                if approved + denied > 0 {
+                    println!(
+                        "  Overall deny rate: {:.1}%",
+                        denied as f64 / (approved + denied) as f64 * 100.0
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `rustant stats cost --month`: a month's LLM cost per workspace and
+/// model, this workspace's spend against its soft cap, and optionally a
+/// chart spec of that spend for the dashboard.
+fn print_month_cost(
+    store: &rustant_core::event_store::EventStore,
+    month: &str,
+    chart: bool,
+    config: &rustant_core::AgentConfig,
+    workspace: &Path,
+) -> anyhow::Result<()> {
+    use rustant_core::event_store::{cost_chart, format_cost, is_month};
+
+    if !is_month(month) {
+        anyhow::bail!("Invalid month '{}'; expected YYYY-MM", month);
+    }
+    let current = workspace.display().to_string();
+    println!("LLM cost, {}:", month);
+    println!(
+        "  {:<28} {:>8} {:>11} {:>11} {:>10}  WORKSPACE",
+        "MODEL", "REQUESTS", "INPUT", "OUTPUT", "COST"
+    );
+    let (mut total, mut requests, mut unpriced) = (0.0, 0, 0);
+    for cost in store.month_by_workspace(month)? {
+        total += cost.cost_usd;
+        requests += cost.requests;
+        unpriced += cost.unpriced_requests;
+        let marker = if cost.workspace == current { "*" } else { "" };
+        println!(
+            "  {:<28} {:>8} {:>11} {:>11} {:>10}  {}{}",
+            cost.model,
+            cost.requests,
+            cost.input_tokens,
+            cost.output_tokens,
+            format_cost(cost.cost_usd, cost.requests, cost.unpriced_requests),
+            cost.workspace,
+            marker
+        );
+    }
+    println!("  Total: {}", format_cost(total, requests, unpriced));
+    if unpriced > 0 {
+        println!(
+            "  {} request(s) went to models with unknown pricing and are not in the total.",
+            unpriced
+        );
+    }
+
+    let days = store.month_by_day(month, Some(&current))?;
+    let cap_usd = config
+        .budget
+        .as_ref()
+        .map(|budget| budget.monthly_soft_cap_usd)
+        .filter(|cap| *cap > 0.0);
+    if let Some(cap_usd) = cap_usd {
+        let spent: f64 = days.iter().map(|d| d.cost_usd).sum();
+        let over = if spent >= cap_usd {
+            " — over the cap"
+        } else {
+            ""
+        };
+        println!(
+            "  This workspace: ${:.2} of its ${:.2} monthly soft cap{}",
+            spent, cap_usd, over
+        );
+    }
+    if chart {
+        let spec = cost_chart(month, &days, cap_usd);
+        println!();
+        println!("{}", serde_json::to_string_pretty(&spec)?);
+    }
+    Ok(())
+}
+
+/// `rustant workspace`: register, list and remove named workspaces.
+fn handle_workspace(action: WorkspaceAction, workspace: &Path) -> anyhow::Result<()> {
+    let registry_path = rustant_core::workspaces::default_workspaces_path()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the rustant data directory"))?;
+    let mut registry = rustant_core::WorkspaceRegistry::load(&registry_path)?;
+    match action {
+        WorkspaceAction::Add { path, name } => {
+            let name = match name {
+                Some(name) => name,
+                None => path
+                    .canonicalize()
+                    .ok()
+                    .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Cannot derive a name from {}; pass --name", path.display())
+                    })?,
+            };
+            let added = registry.add(&name, &path)?.clone();
+            registry.save(&registry_path)?;
+            println!("Workspace '{}' added: {}", added.name, added.path.display());
+            println!(
+                "  Switch with `rustant --workspace-name {}` or `/workspace {}` in the REPL.",
+                added.name, added.name
+            );
+        }
+        WorkspaceAction::List => {
+            if registry.is_empty() {
+                println!("No named workspaces.");
+                println!("Add one with: rustant workspace add <path> --name <name>");
+            }
+            for entry in registry.list() {
+                let marker = if entry.path == workspace { "*" } else { " " };
+                let missing = if entry.path.is_dir() {
+                    ""
+                } else {
+                    " (missing)"
+                };
+                println!(
+                    "{} {:<16} {}{}",
+                    marker,
+                    entry.name,
+                    entry.path.display(),
+                    missing
+                );
+            }
+        }
+        WorkspaceAction::Remove { name } => {
+            let removed = registry.remove(&name)?;
+            registry.save(&registry_path)?;
+            println!(
+                "Workspace '{}' removed; {} was left untouched.",
+                removed.name,
+                removed.path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn handle_policy(action: PolicyAction, workspace: &Path) -> anyhow::Result<()> {
+    use rustant_core::contracts::{CONTRACTS_FILE, ContractSet};
+
+    match action {
+        PolicyAction::Validate { path } => {
+            let (set, source) = match path {
+                Some(path) => (ContractSet::from_file(&path), path.display().to_string()),
+                None => {
+                    let config = rustant_core::config::load_config(Some(workspace), None)
+                        .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+                    (
+                        ContractSet::load(&config.contracts, workspace),
+                        format!("config.toml and .rustant/{}", CONTRACTS_FILE),
+                    )
+                }
+            };
+            let set = set.map_err(|e| anyhow::anyhow!("{}", e))?;
+            if set.is_empty() {
+                println!("No tool contracts defined in {}.", source);
+                return Ok(());
+            }
+            println!("{} tool contract(s) valid in {}:", set.len(), source);
+            for contract in set.iter() {
+                println!(
+                    "  {} [{}] {} precondition(s), {} postcondition(s){}",
+                    contract.name,
+                    contract.tools.join(", "),
+                    contract.precondition_count(),
+                    contract.postcondition_count(),
+                    if contract.description.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" - {}", contract.description)
+                    }
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+fn handle_dev(action: DevAction) -> anyhow::Result<()> {
+    use rustant_core::gateway::{PROTOCOL_VERSION, protocol_schema, protocol_typescript};
+
+    match action {
+        DevAction::GenClient { out } => {
+            std::fs::create_dir_all(&out)?;
+            let schema = protocol_schema();
+            let schema_path = out.join("protocol.schema.json");
+            std::fs::write(&schema_path, serde_json::to_string_pretty(&schema)? + "\n")?;
+            let types_path = out.join("protocol.d.ts");
+            std::fs::write(&types_path, protocol_typescript(&schema))?;
+            println!(
+                "Gateway protocol {} written to {} and {}",
+                PROTOCOL_VERSION,
+                schema_path.display(),
+                types_path.display()
+            );
+            Ok(())
+        }
+    }
+}
+
+async fn handle_cdc(action: CdcCommand, workspace: &Path) -> anyhow::Result<()> {
+    use rustant_core::{CdcAction, CdcFacts, CdcProcessor};
+
+    let config = rustant_core::config::load_config(Some(workspace), None)
+        .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+    let cdc = config.cdc.unwrap_or_default();
+    if cdc.sources.is_empty() {
+        println!("No CDC sources configured. Add [[cdc.sources]] to .rustant/config.toml.");
+        return Ok(());
+    }
+    let mut processor = CdcProcessor::new(cdc, workspace.to_path_buf());
+
+    match action {
+        CdcCommand::Status => {
+            println!(
+                "{:<20} {:<7} {:<20} {:>9} {:>7}",
+                "SOURCE", "TYPE", "LAST SYNC", "RECORDS", "ERRORS"
+            );
+            for source in &processor.config.sources {
+                let kind = match &source.kind {
+                    rustant_core::channels::cdc::CdcSourceKind::Folder { .. } => "folder",
+                    rustant_core::channels::cdc::CdcSourceKind::Sql { .. } => "sql",
+                    rustant_core::channels::cdc::CdcSourceKind::Http { .. } => "http",
+                };
+                let state = processor.state.sources.get(&source.name);
+                let last_sync = state
+                    .and_then(|s| s.last_sync)
+                    .map(|t| {
+                        t.with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| "never".into());
+                println!(
+                    "{:<20} {:<7} {:<20} {:>9} {:>7}{}",
+                    source.name,
+                    kind,
+                    last_sync,
+                    state.map_or(0, |s| s.records_processed),
+                    state.map_or(0, |s| s.errors.len()),
+                    if source.enabled { "" } else { "  (disabled)" }
+                );
+                if let Some(error) = state.and_then(|s| s.errors.last()) {
+                    println!("    last error: {}", error.message);
+                }
+            }
+            Ok(())
+        }
+        CdcCommand::Sync { source, force } => {
+            if let Some(name) = &source
+                && processor.config.source(name).is_none()
+            {
+                anyhow::bail!("Unknown CDC source '{}'", name);
+            }
+            let (actions, outcomes) = processor.sync_sources(force, source.as_deref()).await;
+
+            let mut facts = CdcFacts::load(workspace);
+            let (mut created, mut updated) = (0, 0);
+            for action in &actions {
+                let was_created = match action {
+                    CdcAction::UpsertFact {
+                        source,
+                        key,
+                        content,
+                        tags,
+                    } => facts.upsert(source, key, content, tags),
+                    CdcAction::UpsertInboxItem {
+                        source,
+                        key,
+                        text,
+                        tags,
+                    } => {
+                        rustant_tools::inbox::upsert_synced_item(
+                            workspace.to_path_buf(),
+                            source,
+                            key,
+                            text,
+                            tags,
+                        )
+                        .map_err(|e| anyhow::anyhow!("{}", e))?
+                        .1
+                    }
+                    _ => continue,
+                };
+                if was_created {
+                    created += 1;
+                } else {
+                    updated += 1;
+                }
+            }
+            facts
+                .save(workspace)
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+            if outcomes.is_empty() {
+                println!("No sources due. Use --force to sync now.");
+            }
+            for outcome in &outcomes {
+                match &outcome.error {
+                    Some(e) => println!("  {} failed: {}", outcome.source, e),
+                    None if outcome.snapshot_skipped => {
+                        println!("  {} snapshot position recorded", outcome.source)
+                    }
+                    None => println!(
+                        "  {} {} changed, {} unchanged",
+                        outcome.source, outcome.records, outcome.unchanged
+                    ),
+                }
+            }
+            if !actions.is_empty() {
+                println!("{} created, {} updated.", created, updated);
+            }
+            Ok(())
+        }
+    }
+}
+
+pub async fn handle_eval(action: EvalAction, workspace: &Path) -> anyhow::Result<()> {
+    use futures::StreamExt;
+    use rustant_core::evaluation::{EvalMode, EvalReport, load_scenarios};
+
+    match action {
+        EvalAction::Run {
+            dir,
+            live,
+            concurrency,
+            output,
+            report,
+        } => {
+            let scenarios = load_scenarios(&dir).map_err(|e| anyhow::anyhow!("{}", e))?;
+            if scenarios.is_empty() {
+                println!("No scenarios (*.yaml) found in {}", dir.display());
+                return Ok(());
+            }
+
+            let output = output.unwrap_or_else(|| dir.join(".eval-output"));
+            let workspace_root = output.join("workspaces");
+            let transcript_dir = output.join("transcripts");
+            std::fs::create_dir_all(&transcript_dir)?;
+
+            let config = rustant_core::config::load_config(Some(workspace), None)
+                .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+            let live_provider = if live {
+                Some(
+                    rustant_core::create_provider(&config.llm)
+                        .map_err(|e| anyhow::anyhow!("LLM provider init failed: {}", e))?,
+                )
+            } else {
+                None
+            };
+            let mode = if live { EvalMode::Live } else { EvalMode::Mock };
+
+            println!(
+                "Running {} scenario(s) ({} mode, concurrency {})...",
+                scenarios.len(),
+                if live { "live" } else { "mock" },
+                concurrency.max(1)
+            );
+
+            let started_at = chrono::Utc::now();
+            let results: Vec<_> =
+                futures::stream::iter(scenarios.into_iter().map(|(scenario_dir, scenario)| {
+                    run_eval_scenario(
+                        scenario,
+                        scenario_dir,
+                        config.clone(),
+                        live_provider.clone(),
+                        workspace_root.clone(),
+                        transcript_dir.clone(),
+                    )
+                }))
+                .buffer_unordered(concurrency.max(1))
+                .collect()
+                .await;
+
+            let model = live.then(|| config.llm.model.clone());
+            let report_data = EvalReport::new(started_at, mode, model, results);
+            let report_path = report.unwrap_or_else(|| output.join("report.json"));
+            if let Some(parent) = report_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&report_path, serde_json::to_string_pretty(&report_data)?)?;
+
+            println!("\n{}", report_data.summary());
+            println!("  Report: {}", report_path.display());
+            println!("  Transcripts: {}", transcript_dir.display());
+
+            if !report_data.all_passed() {
+                anyhow::bail!(
+                    "{} of {} scenario(s) failed",
+                    report_data.failed,
+                    report_data.total
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Run one evaluation scenario against the real agent loop in a fresh workspace.
+async fn run_eval_scenario(
+    scenario: rustant_core::evaluation::EvalScenario,
+    scenario_dir: std::path::PathBuf,
+    mut config: rustant_core::AgentConfig,
+    live_provider: Option<std::sync::Arc<dyn rustant_core::LlmProvider>>,
+    workspace_root: std::path::PathBuf,
+    transcript_dir: std::path::PathBuf,
+) -> rustant_core::evaluation::ScenarioResult {
+    use rustant_core::evaluation::{
+        CheckOutcome, ScenarioResult, ScenarioTranscript, evaluate, prepare_workspace,
+    };
+    use std::sync::Arc;
+
+    let started = std::time::Instant::now();
+    let fail_early = |workspace: std::path::PathBuf, detail: String| {
+        ScenarioResult::new(
+            &scenario.name,
+            workspace,
+            vec![CheckOutcome::fail("setup", detail)],
+        )
+    };
+
+    let workspace = match prepare_workspace(&scenario, &scenario_dir, &workspace_root) {
+        Ok(ws) => ws,
+        Err(e) => return fail_early(workspace_root.join(scenario.slug()), e.to_string()),
+    };
+    let provider: Arc<dyn rustant_core::LlmProvider> = match live_provider {
+        Some(p) => p,
+        None => match scenario.mock_provider() {
+            Some(p) => Arc::new(p),
+            None => {
+                return fail_early(
+                    workspace,
+                    "scenario has no mock_responses; run with --live".into(),
+                );
+            }
+        },
+    };
+
+    config.llm.use_streaming = false;
+    if let Some(max) = scenario.budget.max_iterations {
+        config.safety.max_iterations = max;
+    }
+    if let Some(limit) = scenario.budget.max_cost_usd {
+        config.budget = Some(rustant_core::config::BudgetConfig {
+            task_limit_usd: limit,
+            halt_on_exceed: true,
+            ..Default::default()
+        });
+    }
+
+    let callback = Arc::new(rustant_core::agent::RecordingCallback::new());
+    let mut agent = rustant_core::Agent::new(provider, config, callback);
+    let mut registry = rustant_tools::registry::ToolRegistry::new();
+    rustant_tools::register_builtin_tools(&mut registry, workspace.clone());
+    for name in registry.list_names() {
+        if !scenario.tool_permitted(&name) {
+            let _ = registry.unregister(&name);
+        }
+    }
+    crate::repl::register_agent_tools_from_registry(&mut agent, &registry, &workspace);
+
+    let outcome = {
+        let run = agent.process_task(&scenario.task);
+        match scenario.budget.timeout_secs {
+            Some(secs) => tokio::time::timeout(std::time::Duration::from_secs(secs), run)
+                .await
+                .map_err(|_| format!("timed out after {}s", secs))
+                .and_then(|r| r.map_err(|e| e.to_string())),
+            None => run.await.map_err(|e| e.to_string()),
+        }
+    };
+
+    let mut transcript =
+        ScenarioTranscript::from_messages(&scenario.task, &agent.memory().context_messages());
+    match outcome {
+        Ok(result) => {
+            transcript.response = result.response;
+            transcript.iterations = result.iterations;
+        }
+        Err(e) => transcript.error = Some(e),
+    }
+    transcript.usage = *agent.brain().total_usage();
+    transcript.cost = *agent.brain().total_cost();
+
+    let checks = evaluate(&scenario, &workspace, &transcript);
+    let mut result = ScenarioResult::new(&scenario.name, workspace, checks);
+    result.duration_ms = started.elapsed().as_millis() as u64;
+
+    let transcript_path = transcript_dir.join(format!("{}.json", scenario.slug()));
+    match serde_json::to_string_pretty(&transcript) {
+        Ok(json) => match std::fs::write(&transcript_path, json) {
+            Ok(()) => result.transcript_path = Some(transcript_path),
+            Err(e) => tracing::warn!("Failed to save eval transcript: {}", e),
+        },
+        Err(e) => tracing::warn!("Failed to serialize eval transcript: {}", e),
+    }
+    result
+}
+
+/// Connect to configured external MCP servers and log results.
+///
+/// For each server with `auto_connect: true`, spawns the process, performs
+/// the MCP initialize handshake, and lists available tools. Logs warnings
+/// for servers that fail to connect.
+pub async fn connect_mcp_servers(
+    configs: &[rustant_core::ExternalMcpServerConfig],
+) -> Vec<(String, Vec<String>)> {
+    let mut connected = Vec::new();
+
+    for config in configs {
+        if !config.auto_connect {
+            continue;
+        }
+
+        tracing::info!(name = %config.name, command = %config.command, "Connecting to MCP server");
+
+        match rustant_mcp::transport::ProcessTransport::spawn(
+            &config.command,
+            &config.args,
+            &config.env,
+        )
         .await
         {
             Ok((mut transport, _child)) => {
@@ -2067,10 +4484,11 @@ pub async fn connect_mcp_servers(
     connected
 }
 
-/// Run the voice command loop with "hey rustant" wake word detection.
+/// Run the voice command loop with "hey rustant" wake word detection, or
+/// push-to-talk when `[voice] activation = "push_to_talk"`.
 ///
-/// Continuously listens for the wake word, transcribes the command, processes it
-/// through the agent, and speaks the response back.
+/// Continuously listens for the wake word (or waits for Enter), transcribes the
+/// command, processes it through the agent, and speaks the response back.
 #[cfg(feature = "voice")]
 pub async fn run_voice_mode(
     config: rustant_core::AgentConfig,
@@ -2086,10 +4504,15 @@ pub async fn run_voice_mode(
         )
     })?;
 
-    println!("Voice mode active. Say \"hey rustant\" to give a command.");
+    let voice_config = config.voice.clone().unwrap_or_default();
+    let push_to_talk = voice_config.activation == rustant_core::config::VoiceActivation::PushToTalk;
+    if push_to_talk {
+        println!("Voice mode active. Press Enter, then speak your command.");
+    } else {
+        println!("Voice mode active. Say \"hey rustant\" to give a command.");
+    }
     println!("Press Ctrl+C to exit.\n");
 
-    let voice_config = config.voice.clone().unwrap_or_default();
     let stt: Arc<dyn rustant_core::voice::SttProvider> = Arc::new(OpenAiSttProvider::new(&api_key));
     let tts: Arc<dyn rustant_core::voice::TtsProvider> = Arc::new(OpenAiTtsProvider::new(&api_key));
     let wake_detector: Box<dyn rustant_core::voice::WakeWordDetector> =
@@ -2130,7 +4553,15 @@ pub async fn run_voice_mode(
     }
 
     loop {
-        match pipeline.listen_for_command().await {
+        let heard = if push_to_talk {
+            tokio::task::spawn_blocking(|| std::io::stdin().read_line(&mut String::new()))
+                .await??;
+            println!("  Listening...");
+            pipeline.record_command().await
+        } else {
+            pipeline.listen_for_command().await
+        };
+        match heard {
             Ok(Some(command)) => {
                 println!("  Heard: \"{}\"", command);
                 match agent.process_task(&command).await {
@@ -2148,7 +4579,7 @@ pub async fn run_voice_mode(
                 }
             }
             Ok(None) => {
-                // No wake word detected, continue listening
+                // Nothing heard (no wake word, or silence), keep listening
             }
             Err(e) => {
                 eprintln!("Voice error: {}", e);
@@ -2163,6 +4594,56 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[tokio::test]
+    async fn test_briefing_validates_sections_and_delivers_to_canvas() {
+        let dir = TempDir::new().unwrap();
+        let rustant_dir = dir.path().join(".rustant");
+        std::fs::create_dir_all(&rustant_dir).unwrap();
+        let config = "[briefing]\ndelivery = { type = \"canvas\" }\n";
+        std::fs::write(
+            rustant_dir.join("config.toml"),
+            format!("{}\n[[briefing.sections]]\nid = \"horoscope\"\n", config),
+        )
+        .unwrap();
+        let err = handle_briefing(rustant_core::BriefingPeriod::Day, false, dir.path())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("Unknown briefing section 'horoscope'"));
+
+        std::fs::write(rustant_dir.join("config.toml"), config).unwrap();
+        handle_briefing(rustant_core::BriefingPeriod::WeekAhead, true, dir.path())
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_policy_validate_reports_failing_predicate() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("contracts.yaml");
+        std::fs::write(
+            &path,
+            "contracts:\n  - name: guard\n    tools: [shell_exec]\n    preconditions:\n      - arg: timeout_secs\n        min: 10\n        max: 1\n",
+        )
+        .unwrap();
+        let err = handle_policy(PolicyAction::Validate { path: Some(path) }, dir.path())
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Invalid contract 'guard' preconditions[0].min: min (10) is greater than max (1)"
+        );
+
+        let rustant_dir = dir.path().join(".rustant");
+        std::fs::create_dir_all(&rustant_dir).unwrap();
+        std::fs::write(
+            rustant_dir.join("contracts.yaml"),
+            "contracts:\n  - name: guard\n    tools: [shell_exec]\n    preconditions:\n      - arg: command\n        not_contains: [\"rm -rf\"]\n",
+        )
+        .unwrap();
+        handle_policy(PolicyAction::Validate { path: None }, dir.path()).unwrap();
+    }
+
     #[tokio::test]
     async fn test_config_init_creates_file() {
         let dir = TempDir::new().unwrap();
@@ -2241,4 +4722,84 @@ mod tests {
         let result = handle_command(show_cmd, workspace).await;
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_config_trust_reset_scopes() {
+        use rustant_core::trust::{TrustEventKind, TrustLedger, TrustPolicy};
+
+        let dir = TempDir::new().unwrap();
+        let store = dir.path().join("trust.json");
+        let workspace = dir.path().join("project");
+        let policy = TrustPolicy::default();
+        let mut ledger = TrustLedger::new();
+        let now = chrono::Utc::now();
+        let current = workspace.display().to_string();
+        for (tool, ws) in [
+            ("file_write", current.as_str()),
+            ("file_write", "/other"),
+            ("git_commit", "/other"),
+        ] {
+            ledger.record(tool, ws, TrustEventKind::Approved, &policy, now);
+        }
+        ledger.save(&store).unwrap();
+
+        let show = TrustAction::Show {
+            tool: Some("file_write".into()),
+            workspace: None,
+        };
+        handle_config_trust(show, &workspace, &store).unwrap();
+
+        let reset = |target: &[&str]| TrustAction::Reset {
+            target: target.iter().map(|s| s.to_string()).collect(),
+        };
+        handle_config_trust(reset(&["workspace"]), &workspace, &store).unwrap();
+        assert_eq!(TrustLedger::load(&store).unwrap().entries().len(), 2);
+        handle_config_trust(reset(&["git_commit"]), &workspace, &store).unwrap();
+        assert_eq!(TrustLedger::load(&store).unwrap().entries().len(), 1);
+        assert!(handle_config_trust(reset(&["a", "b"]), &workspace, &store).is_err());
+        handle_config_trust(reset(&["all"]), &workspace, &store).unwrap();
+        assert!(TrustLedger::load(&store).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_eval_run_mock_scenario() {
+        let dir = TempDir::new().unwrap();
+        let scenarios = dir.path().join("scenarios");
+        std::fs::create_dir_all(&scenarios).unwrap();
+        std::fs::write(
+            scenarios.join("greeting.yaml"),
+            r#"
+name: greeting
+task: Create hello.txt containing a greeting
+allowed_tools: [file_write, file_read]
+forbidden_tools: [shell_exec]
+assertions:
+  - file_contains: { path: hello.txt, pattern: hello }
+  - tool_called: file_write
+mock_responses:
+  - tool_call: { name: file_write, arguments: { path: hello.txt, content: hello world } }
+  - text: Done
+"#,
+        )
+        .unwrap();
+
+        let output = dir.path().join("out");
+        let command = Commands::Eval {
+            action: EvalAction::Run {
+                dir: scenarios.clone(),
+                live: false,
+                concurrency: 2,
+                output: Some(output.clone()),
+                report: None,
+            },
+        };
+        handle_command(command, dir.path()).await.unwrap();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(output.join("report.json")).unwrap())
+                .unwrap();
+        assert_eq!(report["passed"], 1);
+        assert!(output.join("transcripts/greeting.json").exists());
+        assert!(output.join("workspaces/greeting/hello.txt").exists());
+    }
 }
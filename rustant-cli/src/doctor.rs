@@ -0,0 +1,841 @@
+//! `rustant doctor` — environment and configuration self-diagnosis.
+//!
+//! Checks run concurrently, each under its own timeout, and report pass,
+//! warn or fail. Anything that does not pass carries a one-line remediation.
+//! `--json` prints the same report for scripts.
+
+use futures::FutureExt;
+use futures::future::{BoxFuture, join_all};
+use rustant_core::config::{AgentConfig, LlmConfig};
+use rustant_core::credentials::{
+    CredentialFileConfig, CredentialStore, CredentialStoreKind, FileCredentialStore, FileStoreKey,
+    InMemoryCredentialStore, KEY_FILE_ENV, KeyringCredentialStore,
+};
+use rustant_core::error::LlmError;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Longest any single check may take.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest a `--version` probe of an optional binary may take.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(2);
+/// Port `rustant ui` starts the gateway on unless `--port` is given.
+const UI_GATEWAY_PORT: u16 = 18790;
+/// Free space below which data and log directories get a warning.
+const LOW_DISK_WARN_BYTES: u64 = 1 << 30;
+/// Free space below which they fail.
+const LOW_DISK_FAIL_BYTES: u64 = 100 << 20;
+/// Directory names used by file sync clients.
+const CLOUD_SYNC_FOLDERS: &[(&str, &str)] = &[
+    ("Dropbox", "Dropbox"),
+    ("OneDrive", "OneDrive"),
+    ("Google Drive", "Google Drive"),
+    ("GoogleDrive", "Google Drive"),
+    ("Mobile Documents", "iCloud Drive"),
+    ("iCloud Drive", "iCloud Drive"),
+    ("Box Sync", "Box"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Outcome of one diagnostic check.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Check {
+    pub group: &'static str,
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl Check {
+    fn pass(group: &'static str, name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            group,
+            name: name.into(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            remediation: None,
+            duration_ms: 0,
+        }
+    }
+
+    fn warn(
+        group: &'static str,
+        name: impl Into<String>,
+        detail: impl Into<String>,
+        remediation: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            remediation: Some(remediation.into()),
+            ..Self::pass(group, name, detail)
+        }
+    }
+
+    fn fail(
+        group: &'static str,
+        name: impl Into<String>,
+        detail: impl Into<String>,
+        remediation: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            remediation: Some(remediation.into()),
+            ..Self::pass(group, name, detail)
+        }
+    }
+}
+
+/// All check results with their counts.
+#[derive(Debug, Serialize)]
+pub(crate) struct Report {
+    pub checks: Vec<Check>,
+    pub passed: usize,
+    pub warnings: usize,
+    pub failures: usize,
+    pub duration_ms: u64,
+}
+
+impl Report {
+    fn new(checks: Vec<Check>, started: Instant) -> Self {
+        let count = |status| checks.iter().filter(|c| c.status == status).count();
+        Self {
+            passed: count(CheckStatus::Pass),
+            warnings: count(CheckStatus::Warn),
+            failures: count(CheckStatus::Fail),
+            duration_ms: started.elapsed().as_millis() as u64,
+            checks,
+        }
+    }
+
+    fn print(&self) {
+        println!("Rustant Doctor");
+        println!("══════════════════════════════");
+        let mut group = "";
+        for check in &self.checks {
+            if check.group != group {
+                group = check.group;
+                println!("\n\x1b[1m{}\x1b[0m", group);
+            }
+            let marker = match check.status {
+                CheckStatus::Pass => "\x1b[32m✓\x1b[0m",
+                CheckStatus::Warn => "\x1b[33m!\x1b[0m",
+                CheckStatus::Fail => "\x1b[31m✗\x1b[0m",
+            };
+            println!("  {} {:<22} {}", marker, check.name, check.detail);
+            if let Some(remediation) = &check.remediation {
+                println!("      → {}", remediation);
+            }
+        }
+        println!(
+            "\n{} passed, {} warning(s), {} failed ({:.1}s)",
+            self.passed,
+            self.warnings,
+            self.failures,
+            self.duration_ms as f64 / 1000.0
+        );
+    }
+}
+
+/// Run every check and print the report. Fails if any check failed.
+pub(crate) async fn run(workspace: &Path, json: bool) -> anyhow::Result<()> {
+    let started = Instant::now();
+    let (mut checks, config) = check_config(workspace);
+    let (store_check, store) = check_credential_store(&config).await;
+    checks.push(store_check);
+
+    let mut pending: Vec<BoxFuture<'static, Vec<Check>>> = Vec::new();
+    for llm in configured_providers(&config.llm) {
+        let name = llm.provider.clone();
+        pending.push(timed("Providers", name, check_provider(llm, store.clone())));
+    }
+    pending.push(timed(
+        "Filesystem",
+        "workspace",
+        check_workspace(workspace.to_path_buf()),
+    ));
+    if let Some(data_dir) = directories::ProjectDirs::from("dev", "rustant", "rustant")
+        .map(|d| d.data_dir().to_path_buf())
+    {
+        pending.push(timed(
+            "Filesystem",
+            "data dir",
+            check_dir("data dir", data_dir.clone()),
+        ));
+        pending.push(timed(
+            "Filesystem",
+            "log dir",
+            check_dir("log dir", data_dir.join("logs")),
+        ));
+    }
+    pending.push(timed("Binaries", "binaries", check_binaries()));
+    let gateway = config.gateway.clone().unwrap_or_default();
+    let ports = if config.gateway.is_some() && gateway.port != UI_GATEWAY_PORT {
+        vec![gateway.port, UI_GATEWAY_PORT]
+    } else {
+        vec![UI_GATEWAY_PORT]
+    };
+    for port in ports {
+        pending.push(timed(
+            "Gateway",
+            format!("port {}", port),
+            check_port(gateway.host.clone(), port),
+        ));
+    }
+    let channels = config.channels.clone().unwrap_or_default();
+    if let Some(slack) = channels.slack {
+        let token = resolve_secret(&slack.bot_token, &store);
+        pending.push(timed("Channels", "slack", async move {
+            check_channel("slack", token, |t| async move {
+                crate::channel_setup::validate_slack_token(&t).await
+            })
+            .await
+        }));
+    }
+    if let Some(telegram) = channels.telegram {
+        let token = resolve_secret(&telegram.bot_token, &store);
+        pending.push(timed("Channels", "telegram", async move {
+            check_channel("telegram", token, |t| async move {
+                crate::channel_setup::validate_telegram_token(&t).await
+            })
+            .await
+        }));
+    }
+    if let Some(discord) = channels.discord {
+        let token = if discord.bot_token.is_empty() {
+            Err("no token configured".to_string())
+        } else {
+            Ok(discord.bot_token)
+        };
+        pending.push(timed("Channels", "discord", async move {
+            check_channel("discord", token, |t| async move {
+                crate::channel_setup::validate_discord_token(&t).await
+            })
+            .await
+        }));
+    }
+
+    checks.extend(join_all(pending).await.into_iter().flatten());
+    let report = Report::new(checks, started);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        report.print();
+    }
+    if report.failures > 0 {
+        anyhow::bail!("{} doctor check(s) failed", report.failures);
+    }
+    Ok(())
+}
+
+/// Run `check` under [`CHECK_TIMEOUT`], recording how long it took.
+fn timed(
+    group: &'static str,
+    name: impl Into<String>,
+    check: impl Future<Output = Vec<Check>> + Send + 'static,
+) -> BoxFuture<'static, Vec<Check>> {
+    let name = name.into();
+    async move {
+        let started = Instant::now();
+        let mut checks = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+            Ok(checks) => checks,
+            Err(_) => vec![Check::fail(
+                group,
+                name,
+                format!("no answer within {}s", CHECK_TIMEOUT.as_secs()),
+                "Check network access, proxies and firewalls",
+            )],
+        };
+        let elapsed = started.elapsed().as_millis() as u64;
+        for check in &mut checks {
+            check.duration_ms = elapsed;
+        }
+        checks
+    }
+    .boxed()
+}
+
+/// Each config file parses, and together they produce valid settings.
+fn check_config(workspace: &Path) -> (Vec<Check>, AgentConfig) {
+    let mut checks = Vec::new();
+    let files: Vec<PathBuf> = directories::ProjectDirs::from("dev", "rustant", "rustant")
+        .map(|d| d.config_dir().join("config.toml"))
+        .into_iter()
+        .chain([workspace.join(".rustant").join("config.toml")])
+        .filter(|p| p.exists())
+        .collect();
+    if files.is_empty() {
+        checks.push(Check::warn(
+            "Configuration",
+            "config file",
+            "none found; using defaults",
+            "Run `rustant setup` to create one",
+        ));
+    }
+    for file in &files {
+        let parsed = std::fs::read_to_string(file)
+            .map_err(|e| e.to_string())
+            .and_then(|c| c.parse::<toml::Table>().map_err(|e| e.to_string()));
+        checks.push(match parsed {
+            Ok(_) => Check::pass("Configuration", "config file", file.display().to_string()),
+            Err(e) => Check::fail(
+                "Configuration",
+                "config file",
+                format!("{}: {}", file.display(), first_line(&e)),
+                format!("Fix the TOML syntax in {}", file.display()),
+            ),
+        });
+    }
+    match rustant_core::config::load_config(Some(workspace), None) {
+        Ok(config) => {
+            checks.push(Check::pass(
+                "Configuration",
+                "settings",
+                format!(
+                    "provider {}, model {}",
+                    config.llm.provider, config.llm.model
+                ),
+            ));
+            (checks, config)
+        }
+        Err(e) => {
+            checks.push(Check::fail(
+                "Configuration",
+                "settings",
+                first_line(&e.to_string()),
+                "Fix the setting named above, or check RUSTANT_* environment overrides",
+            ));
+            (checks, AgentConfig::default())
+        }
+    }
+}
+
+/// Open the configured credential store without prompting for a passphrase.
+/// When it cannot be opened, keys resolve from the environment only.
+async fn check_credential_store(config: &AgentConfig) -> (Check, Arc<dyn CredentialStore>) {
+    let kind = config.credential_store;
+    let file = config.credential_file.clone().unwrap_or_default();
+    let opened = tokio::time::timeout(
+        CHECK_TIMEOUT,
+        tokio::task::spawn_blocking(move || open_store_quietly(kind, &file)),
+    )
+    .await;
+    match opened {
+        Ok(Ok((check, store))) => (check, store),
+        _ => (
+            Check::fail(
+                "Credentials",
+                "credential store",
+                "did not respond",
+                "Unlock the OS keychain, or set credential_store = \"file\"",
+            ),
+            Arc::new(InMemoryCredentialStore::new()),
+        ),
+    }
+}
+
+fn open_store_quietly(
+    kind: CredentialStoreKind,
+    file: &CredentialFileConfig,
+) -> (Check, Arc<dyn CredentialStore>) {
+    let use_keyring = match kind {
+        CredentialStoreKind::Keyring => true,
+        CredentialStoreKind::File => false,
+        CredentialStoreKind::Auto => KeyringCredentialStore::is_available(),
+    };
+    if use_keyring {
+        if KeyringCredentialStore::is_available() {
+            return (
+                Check::pass("Credentials", "credential store", "OS keychain"),
+                Arc::new(KeyringCredentialStore::new()),
+            );
+        }
+        return (
+            Check::fail(
+                "Credentials",
+                "credential store",
+                "OS keychain not reachable",
+                "Start a Secret Service (e.g. gnome-keyring), or set credential_store = \"file\"",
+            ),
+            Arc::new(InMemoryCredentialStore::new()),
+        );
+    }
+    let path = file.resolved_path();
+    let Some(key) = FileStoreKey::from_env(file) else {
+        return (
+            Check::warn(
+                "Credentials",
+                "credential store",
+                format!("{} is locked (no key file or passphrase)", path.display()),
+                format!("Set {} to a key file to check stored keys", KEY_FILE_ENV),
+            ),
+            Arc::new(InMemoryCredentialStore::new()),
+        );
+    };
+    match FileCredentialStore::open(&path, key) {
+        Ok(store) => (
+            Check::pass(
+                "Credentials",
+                "credential store",
+                format!("encrypted file {}", path.display()),
+            ),
+            Arc::new(store),
+        ),
+        Err(e) => (
+            Check::fail(
+                "Credentials",
+                "credential store",
+                e.to_string(),
+                "Check the key file or passphrase for the credential file",
+            ),
+            Arc::new(InMemoryCredentialStore::new()),
+        ),
+    }
+}
+
+/// The primary provider and every fallback, as full provider configs.
+fn configured_providers(llm: &LlmConfig) -> Vec<LlmConfig> {
+    std::iter::once(llm.clone())
+        .chain(llm.fallback_providers.iter().map(|f| LlmConfig {
+            provider: f.provider.clone(),
+            model: f.model.clone(),
+            api_key_env: f.api_key_env.clone(),
+            base_url: f.base_url.clone(),
+            credential_store_key: None,
+            ..llm.clone()
+        }))
+        .collect()
+}
+
+fn is_ollama(llm: &LlmConfig) -> bool {
+    llm.provider == "ollama"
+        || llm
+            .base_url
+            .as_deref()
+            .is_some_and(|u| u.contains(":11434"))
+}
+
+/// The provider's key resolves, and a cheap models-list request succeeds.
+async fn check_provider(llm: LlmConfig, store: Arc<dyn CredentialStore>) -> Vec<Check> {
+    let name = format!("{} ({})", llm.provider, llm.model);
+    if is_ollama(&llm) {
+        return vec![check_ollama(&llm, &name).await];
+    }
+    let key = match rustant_core::providers::resolve_auth(&llm, store.as_ref()).await {
+        Ok(key) => key,
+        Err(e) => {
+            return vec![Check::fail(
+                "Providers",
+                name,
+                format!("no API key: {}", e),
+                format!(
+                    "Set {} or run `rustant setup` to store a key",
+                    llm.api_key_env
+                ),
+            )];
+        }
+    };
+    let started = Instant::now();
+    let result = match llm.provider.as_str() {
+        "anthropic" => rustant_core::providers::models::fetch_anthropic_models(&key)
+            .await
+            .map(drop),
+        "gemini" => rustant_core::providers::models::fetch_gemini_models(&key)
+            .await
+            .map(drop),
+        _ => rustant_core::providers::models::fetch_openai_models(&key, llm.base_url.as_deref())
+            .await
+            .map(drop),
+    };
+    vec![match result {
+        Ok(()) => Check::pass(
+            "Providers",
+            name,
+            format!(
+                "key resolved, reachable in {}ms",
+                started.elapsed().as_millis()
+            ),
+        ),
+        Err(LlmError::AuthFailed { .. }) => Check::fail(
+            "Providers",
+            name,
+            "the API key was rejected",
+            "Replace the key with `rustant setup`",
+        ),
+        Err(e) => Check::fail(
+            "Providers",
+            name,
+            first_line(&e.to_string()),
+            "Check network access and [llm] base_url",
+        ),
+    }]
+}
+
+/// The Ollama server answers and has the configured model pulled.
+async fn check_ollama(llm: &LlmConfig, name: &str) -> Check {
+    let base = llm
+        .base_url
+        .as_deref()
+        .unwrap_or("http://localhost:11434/v1")
+        .trim_end_matches('/');
+    let root = base.strip_suffix("/v1").unwrap_or(base);
+    let started = Instant::now();
+    let response = match reqwest::Client::new()
+        .get(format!("{}/api/tags", root))
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(_) => {
+            return Check::fail(
+                "Providers",
+                name,
+                format!("Ollama not reachable at {}", root),
+                "Start it with `ollama serve`",
+            );
+        }
+    };
+    let elapsed = started.elapsed().as_millis();
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    let pulled = body["models"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m["name"].as_str())
+        .any(|n| model_matches(n, &llm.model));
+    if pulled {
+        Check::pass(
+            "Providers",
+            name,
+            format!("Ollama reachable in {}ms, model pulled", elapsed),
+        )
+    } else {
+        Check::fail(
+            "Providers",
+            name,
+            format!("model '{}' is not pulled", llm.model),
+            format!("Run `ollama pull {}`", llm.model),
+        )
+    }
+}
+
+/// Whether an Ollama model tag names `model` (`llama3` matches `llama3:latest`).
+fn model_matches(tag: &str, model: &str) -> bool {
+    tag == model || tag.strip_suffix(":latest") == Some(model)
+}
+
+/// The workspace is writable and not inside a file-sync folder.
+async fn check_workspace(workspace: PathBuf) -> Vec<Check> {
+    let mut checks = vec![match probe_writable(&workspace) {
+        Ok(()) => Check::pass("Filesystem", "workspace", workspace.display().to_string()),
+        Err(e) => Check::fail(
+            "Filesystem",
+            "workspace",
+            format!("{} is not writable: {}", workspace.display(), e),
+            "Fix the directory permissions or run rustant from a writable project",
+        ),
+    }];
+    if is_conflict_copy(&workspace) {
+        checks.push(Check::fail(
+            "Filesystem",
+            "cloud sync",
+            "workspace is a sync conflict copy",
+            "Resolve the sync conflict and open the original project directory",
+        ));
+    } else if let Some(service) = cloud_sync_service(&workspace) {
+        checks.push(Check::warn(
+            "Filesystem",
+            "cloud sync",
+            format!("workspace is inside a {} folder", service),
+            "Exclude .rustant/ from sync, or move the project out of the synced folder",
+        ));
+    }
+    checks
+}
+
+/// `dir` can be created and written, with enough free space.
+async fn check_dir(name: &'static str, dir: PathBuf) -> Vec<Check> {
+    if let Err(e) = std::fs::create_dir_all(&dir).and_then(|()| probe_writable(&dir)) {
+        return vec![Check::fail(
+            "Filesystem",
+            name,
+            format!("{} is not writable: {}", dir.display(), e),
+            format!("Fix the permissions of {}", dir.display()),
+        )];
+    }
+    let free = free_bytes(&dir).await;
+    let detail = match free {
+        Some(bytes) => format!("{} ({} free)", dir.display(), format_bytes(bytes)),
+        None => dir.display().to_string(),
+    };
+    vec![match free {
+        Some(bytes) if bytes < LOW_DISK_FAIL_BYTES => Check::fail(
+            "Filesystem",
+            name,
+            detail,
+            "Free disk space; sessions and logs cannot be saved",
+        ),
+        Some(bytes) if bytes < LOW_DISK_WARN_BYTES => {
+            Check::warn("Filesystem", name, detail, "Free disk space soon")
+        }
+        _ => Check::pass("Filesystem", name, detail),
+    }]
+}
+
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(".rustant-doctor-probe");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+/// Free bytes on the filesystem holding `dir`, from `df`.
+async fn free_bytes(dir: &Path) -> Option<u64> {
+    let output = tokio::process::Command::new("df")
+        .arg("-Pk")
+        .arg(dir)
+        .output()
+        .await
+        .ok()?;
+    parse_df_available(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Available bytes from `df -Pk` output.
+fn parse_df_available(output: &str) -> Option<u64> {
+    let fields: Vec<&str> = output.lines().nth(1)?.split_whitespace().collect();
+    fields.get(3)?.parse::<u64>().ok().map(|kb| kb * 1024)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const GIB: f64 = (1u64 << 30) as f64;
+    const MIB: f64 = (1u64 << 20) as f64;
+    if bytes as f64 >= GIB {
+        format!("{:.1} GiB", bytes as f64 / GIB)
+    } else {
+        format!("{:.0} MiB", bytes as f64 / MIB)
+    }
+}
+
+/// The file sync service whose folder contains `path`, if any.
+fn cloud_sync_service(path: &Path) -> Option<&'static str> {
+    path.components().find_map(|c| {
+        let name = c.as_os_str().to_string_lossy();
+        CLOUD_SYNC_FOLDERS
+            .iter()
+            .find(|(folder, _)| name == *folder || name.starts_with(&format!("{} (", folder)))
+            .map(|(_, service)| *service)
+    })
+}
+
+/// Whether `path` is inside a copy a sync client made for a conflict.
+fn is_conflict_copy(path: &Path) -> bool {
+    path.components().any(|c| {
+        let name = c.as_os_str().to_string_lossy().to_lowercase();
+        name.contains("conflicted copy")
+            || name.contains("(conflict")
+            || name.contains("-conflict-")
+    })
+}
+
+/// git and the language servers the LSP tools use, with versions.
+async fn check_binaries() -> Vec<Check> {
+    let mut commands: Vec<String> = {
+        let registry = rustant_tools::lsp::discovery::ServerRegistry::with_defaults();
+        registry
+            .list_languages()
+            .iter()
+            .filter_map(|l| registry.get(l).map(|c| c.command.clone()))
+            .collect()
+    };
+    commands.sort();
+    commands.dedup();
+
+    let git = binary_version("git".to_string());
+    let servers = join_all(commands.into_iter().map(binary_version));
+    let (git, servers) = futures::join!(git, servers);
+
+    let mut checks = vec![match git {
+        Some((_, version)) => Check::pass("Binaries", "git", version),
+        None => Check::warn(
+            "Binaries",
+            "git",
+            "not found",
+            "Install git; checkpoints, undo and the git tools need it",
+        ),
+    }];
+    let found: Vec<String> = servers
+        .into_iter()
+        .flatten()
+        .map(|(command, version)| format!("{} ({})", command, version))
+        .collect();
+    checks.push(if found.is_empty() {
+        Check::warn(
+            "Binaries",
+            "language servers",
+            "none found",
+            "Install a language server (e.g. rust-analyzer) for the LSP tools",
+        )
+    } else {
+        Check::pass("Binaries", "language servers", found.join(", "))
+    });
+    checks
+}
+
+/// First line of `command --version`, if the binary runs.
+async fn binary_version(command: String) -> Option<(String, String)> {
+    let output = tokio::time::timeout(
+        VERSION_TIMEOUT,
+        tokio::process::Command::new(&command)
+            .arg("--version")
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let version = stdout
+        .lines()
+        .chain(stderr.lines())
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("installed")
+        .to_string();
+    Some((command, version))
+}
+
+/// The gateway port can be bound.
+async fn check_port(host: String, port: u16) -> Vec<Check> {
+    let name = format!("port {}", port);
+    vec![
+        match tokio::net::TcpListener::bind((host.as_str(), port)).await {
+            Ok(_) => Check::pass("Gateway", name, format!("{}:{} is free", host, port)),
+            Err(e) => Check::warn(
+                "Gateway",
+                name,
+                format!("{}:{} is unavailable: {}", host, port, e),
+                "Fine if a rustant gateway is already running; otherwise stop the other process or change [gateway] port",
+            ),
+        },
+    ]
+}
+
+fn resolve_secret(
+    secret: &rustant_core::secret_ref::SecretRef,
+    store: &Arc<dyn CredentialStore>,
+) -> Result<String, String> {
+    rustant_core::secret_ref::SecretResolver::resolve(secret, store.as_ref())
+        .map_err(|e| e.to_string())
+}
+
+/// The channel's token resolves and passes the service's auth test.
+async fn check_channel<F, Fut>(
+    name: &'static str,
+    token: Result<String, String>,
+    auth_test: F,
+) -> Vec<Check>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = anyhow::Result<String>>,
+{
+    let token = match token {
+        Ok(token) => token,
+        Err(e) => {
+            return vec![Check::fail(
+                "Channels",
+                name,
+                format!("token not resolvable: {}", e),
+                format!("Run `rustant setup channels` to configure {}", name),
+            )];
+        }
+    };
+    vec![match auth_test(token).await {
+        Ok(detail) => Check::pass("Channels", name, detail),
+        Err(e) => Check::fail(
+            "Channels",
+            name,
+            first_line(&e.to_string()),
+            format!("Replace the {} token with `rustant setup channels`", name),
+        ),
+    }]
+}
+
+fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cloud_sync_and_conflict_detection() {
+        assert_eq!(
+            cloud_sync_service(Path::new("/Users/a/Dropbox/code/app")),
+            Some("Dropbox")
+        );
+        assert_eq!(
+            cloud_sync_service(Path::new("/Users/a/OneDrive (Acme)/app")),
+            Some("OneDrive")
+        );
+        assert_eq!(
+            cloud_sync_service(Path::new(
+                "/Users/a/Library/Mobile Documents/com~apple~CloudDocs/app"
+            )),
+            Some("iCloud Drive")
+        );
+        assert_eq!(cloud_sync_service(Path::new("/home/a/code/app")), None);
+        assert!(is_conflict_copy(Path::new(
+            "/Users/a/Dropbox/app (a's conflicted copy 2026-10-01)"
+        )));
+        assert!(!is_conflict_copy(Path::new("/home/a/code/app")));
+    }
+
+    #[test]
+    fn test_parse_df_available() {
+        let output = "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                      /dev/sda1 100000 40000 60000 40% /\n";
+        assert_eq!(parse_df_available(output), Some(60000 * 1024));
+        assert_eq!(parse_df_available(""), None);
+        assert_eq!(format_bytes(60000 * 1024), "59 MiB");
+        assert_eq!(format_bytes(3 << 30), "3.0 GiB");
+    }
+
+    #[test]
+    fn test_model_matches_ollama_tags() {
+        assert!(model_matches("llama3:latest", "llama3"));
+        assert!(model_matches("qwen2.5:7b", "qwen2.5:7b"));
+        assert!(!model_matches("qwen2.5:14b", "qwen2.5:7b"));
+    }
+
+    #[test]
+    fn test_report_counts_and_json() {
+        let checks = vec![
+            Check::pass("Filesystem", "workspace", "/tmp/x"),
+            Check::warn("Binaries", "git", "not found", "Install git"),
+            Check::fail(
+                "Providers",
+                "openai (gpt-4o)",
+                "no API key",
+                "Set OPENAI_API_KEY",
+            ),
+        ];
+        let report = Report::new(checks, Instant::now());
+        assert_eq!((report.passed, report.warnings, report.failures), (1, 1, 1));
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["checks"][2]["status"], "fail");
+        assert_eq!(json["checks"][2]["remediation"], "Set OPENAI_API_KEY");
+        assert!(json["checks"][0].get("remediation").is_none());
+    }
+}
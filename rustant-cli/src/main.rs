@@ -4,17 +4,34 @@
 
 pub(crate) mod channel_setup;
 pub mod commands;
+mod doctor;
 mod repl;
 mod repl_input;
+mod repl_markdown;
 pub(crate) mod setup;
 pub(crate) mod slash;
 mod tui;
 
 use clap::Parser;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{EnvFilter, Layer};
+use tracing_subscriber::{EnvFilter, Layer, reload};
+
+/// Replaces the stderr log filter; set when no `-v`/`-q` flag fixed it.
+type FilterReloader = Box<dyn Fn(EnvFilter) -> Result<(), String> + Send + Sync>;
+static STDERR_FILTER: OnceLock<FilterReloader> = OnceLock::new();
+
+/// Swap the stderr log filter at runtime, e.g. when `[logging] filter`
+/// changes during a gateway config reload. `None` restores the default.
+pub(crate) fn set_log_filter(filter: Option<&str>) -> Result<(), String> {
+    let reloader = STDERR_FILTER
+        .get()
+        .ok_or("the log level was set with -v/-q on the command line")?;
+    let filter = EnvFilter::try_new(filter.unwrap_or("warn")).map_err(|e| e.to_string())?;
+    reloader(filter)
+}
 
 /// Rustant: Your Rust-Powered Autonomous Assistant
 #[derive(Parser, Debug)]
@@ -31,6 +48,10 @@ struct Cli {
     #[arg(short, long, default_value = ".")]
     workspace: PathBuf,
 
+    /// Named workspace to use instead of --workspace (see `rustant workspace`)
+    #[arg(long, conflicts_with = "workspace")]
+    workspace_name: Option<String>,
+
     /// Configuration file path
     #[arg(short, long)]
     config: Option<PathBuf>,
@@ -43,6 +64,10 @@ struct Cli {
     #[arg(short = 'a', long)]
     approval: Option<String>,
 
+    /// Persona to start with (e.g. ops-sre, writing-assistant)
+    #[arg(long)]
+    persona: Option<String>,
+
     /// Increase verbosity (-v, -vv, -vvv)
     #[arg(short, long, action = clap::ArgAction::Count)]
     verbose: u8,
@@ -55,6 +80,10 @@ struct Cli {
     #[arg(long)]
     voice: bool,
 
+    /// Keep the usual model past the workspace's monthly soft cap
+    #[arg(long)]
+    ignore_cost_cap: bool,
+
     /// Subcommand
     #[command(subcommand)]
     command: Option<Commands>,
@@ -67,20 +96,35 @@ pub enum Commands {
         #[command(subcommand)]
         action: ConfigAction,
     },
-    /// Interactive provider setup wizard
-    Setup,
+    /// Interactive setup wizard (provider, then optional channels and voice)
+    Setup {
+        /// Run only one section: provider, channels, or voice
+        #[arg(long)]
+        section: Option<String>,
+    },
     /// Smart project initialization: detects project type, generates optimal config
     Init,
+    /// Diagnose configuration, credentials, providers and the local environment
+    Doctor {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Resume a previous session (most recent, or by name)
     Resume {
         /// Session name or ID to resume (omit for most recent)
         session: Option<String>,
+        /// Continue the workspace's interrupted plan task before prompting
+        #[arg(long)]
+        continue_task: bool,
     },
-    /// List saved sessions
+    /// List saved sessions, or replay one turn by turn
     Sessions {
         /// Maximum number of sessions to show
         #[arg(short, long, default_value = "10")]
         limit: usize,
+        #[command(subcommand)]
+        action: Option<SessionsAction>,
     },
     /// Manage messaging channels
     Channel {
@@ -118,6 +162,12 @@ pub enum Commands {
         #[arg(short, long, default_value = "18790")]
         port: u16,
     },
+    /// Inspect or reload the gateway started by `rustant ui`, and pair devices
+    #[command(alias = "daemon")]
+    Gateway {
+        #[command(subcommand)]
+        action: GatewayAction,
+    },
     /// Canvas operations (push, clear, snapshot)
     Canvas {
         #[command(subcommand)]
@@ -138,6 +188,191 @@ pub enum Commands {
         #[command(subcommand)]
         action: UpdateAction,
     },
+    /// Run scenario-based agent evaluations
+    Eval {
+        #[command(subcommand)]
+        action: EvalAction,
+    },
+    /// Inspect tool contracts (config.toml and .rustant/contracts.yaml)
+    Policy {
+        #[command(subcommand)]
+        action: PolicyAction,
+    },
+    /// Sync and inspect change-data-capture sources ([cdc.sources])
+    Cdc {
+        #[command(subcommand)]
+        action: CdcCommand,
+    },
+    /// Generate the daily briefing (sections configured under [briefing])
+    Briefing {
+        /// Cover the next 7 days instead of today
+        #[arg(long)]
+        week_ahead: bool,
+        /// Also send it to the configured [briefing.delivery] destination
+        #[arg(long)]
+        deliver: bool,
+    },
+    /// Inspect the workspace index ([index])
+    Index {
+        #[command(subcommand)]
+        action: IndexAction,
+    },
+    /// Report on agent activity recorded in the event store ([event_store])
+    Stats {
+        #[command(subcommand)]
+        report: Option<StatsReport>,
+        /// Days of history to cover
+        #[arg(long, default_value = "7")]
+        days: u32,
+        /// Run a read-only SQL query against the event store instead of a report
+        #[arg(long)]
+        sql: Option<String>,
+    },
+    /// Manage named workspaces
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceAction,
+    },
+    /// Developer tools for working on Rustant itself
+    Dev {
+        #[command(subcommand)]
+        action: DevAction,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum IndexAction {
+    /// Show what indexing skips and why
+    Status {
+        /// Explain whether this path is indexed, ignored, truncated or absent
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum StatsReport {
+    /// Tool calls and failure rates
+    Tools {
+        /// Show this tool's failures grouped by command or path
+        #[arg(long)]
+        tool: Option<String>,
+    },
+    /// LLM cost and tokens by day and model, or by workspace for a month
+    Cost {
+        /// Report this calendar month (YYYY-MM, UTC) per workspace and model
+        #[arg(long)]
+        month: Option<String>,
+        /// Also print a chart spec of this workspace's spend over the month
+        #[arg(long, requires = "month")]
+        chart: bool,
+    },
+    /// Workspaces with the most tasks
+    Workspaces,
+    /// Approval decisions and deny rate per tool
+    Approvals,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum WorkspaceAction {
+    /// Register a directory under a name
+    Add {
+        /// Workspace directory
+        path: PathBuf,
+        /// Name to switch to it by (default: the directory name)
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// List named workspaces
+    List,
+    /// Unregister a workspace (its files are left alone)
+    Remove {
+        /// Workspace name
+        name: String,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum SessionsAction {
+    /// Step through a recorded session turn by turn
+    Replay {
+        /// Session name or ID (prefix match)
+        session: String,
+        /// Re-run each turn against the current model and config, with tool
+        /// results taken from the recording, and report divergences
+        #[arg(long)]
+        reexecute: bool,
+        /// Turn to start at (1-based)
+        #[arg(long)]
+        turn: Option<usize>,
+    },
+    /// Export sessions as decrypted JSON
+    Export {
+        /// Session name or ID (prefix match); all sessions when omitted
+        session: Option<String>,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Export only index metadata (names, timestamps, token counts); works
+        /// without the session key
+        #[arg(long)]
+        metadata_only: bool,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum PolicyAction {
+    /// Validate tool contracts and list what each one checks
+    Validate {
+        /// Contracts YAML file to check (default: config plus workspace contracts)
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum DevAction {
+    /// Write the gateway protocol's JSON Schema and TypeScript declarations
+    GenClient {
+        /// Output directory
+        #[arg(long, default_value = "rustant-ui/frontend")]
+        out: PathBuf,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum CdcCommand {
+    /// Show last sync, records processed and pending errors per source
+    Status,
+    /// Sync sources now and apply their records to facts and the inbox
+    Sync {
+        /// Only sync this source
+        #[arg(long)]
+        source: Option<String>,
+        /// Sync even if the source is not due yet
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum EvalAction {
+    /// Run every scenario (*.yaml) in a directory and report pass/fail
+    Run {
+        /// Directory containing scenario YAML files
+        dir: PathBuf,
+        /// Use the configured live model instead of scripted mock responses
+        #[arg(long)]
+        live: bool,
+        /// Maximum number of scenarios to run concurrently
+        #[arg(short = 'j', long, default_value = "4")]
+        concurrency: usize,
+        /// Output directory for workspaces, transcripts, and report (default: <dir>/.eval-output)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Write the JSON report to this path (default: <output>/report.json)
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -162,6 +397,31 @@ pub enum SkillAction {
         /// Directory to scan for skill files
         #[arg(short, long)]
         dir: Option<String>,
+        /// Skip checking installed skills' sources for updates
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Install a skill from a path, git repository or HTTPS URL
+    Install {
+        /// SKILL.md path or directory, git URL (optionally #branch-or-tag), or HTTPS URL
+        source: String,
+        /// Skip the install confirmation (a required second confirmation is still asked)
+        #[arg(short, long)]
+        yes: bool,
+        /// Skills directory to install into
+        #[arg(short, long)]
+        dir: Option<String>,
+    },
+    /// Re-fetch installed skills from their recorded source
+    Update {
+        /// Skill to update (all installed skills when omitted)
+        name: Option<String>,
+        /// Skip the update confirmation (a required second confirmation is still asked)
+        #[arg(short, long)]
+        yes: bool,
+        /// Skills directory
+        #[arg(short, long)]
+        dir: Option<String>,
     },
     /// Show details of a skill
     Info {
@@ -195,12 +455,78 @@ pub enum PluginAction {
     },
 }
 
+#[derive(clap::Subcommand, Debug)]
+pub enum GatewayAction {
+    /// Show gateway status and the result of the last config reload
+    Status {
+        /// Gateway port
+        #[arg(short, long, default_value = "18790")]
+        port: u16,
+    },
+    /// Re-read configuration and apply the settings that can change live
+    Reload {
+        /// Gateway port
+        #[arg(short, long, default_value = "18790")]
+        port: u16,
+    },
+    /// Show a QR code that pairs a phone or browser with the gateway
+    Pair {
+        /// What the device may do: status, approvals, or tasks
+        #[arg(short, long, default_value = "status")]
+        scope: String,
+        /// Also write the QR code as a PNG image
+        #[arg(long)]
+        png: Option<PathBuf>,
+        /// Gateway port
+        #[arg(short, long, default_value = "18790")]
+        port: u16,
+    },
+    /// List paired devices
+    Devices {
+        /// Gateway port
+        #[arg(short, long, default_value = "18790")]
+        port: u16,
+    },
+    /// Revoke a paired device
+    Revoke {
+        /// Device ID or name
+        device: String,
+        /// Gateway port
+        #[arg(short, long, default_value = "18790")]
+        port: u16,
+    },
+    /// List connected clients with their address, credential and activity
+    Clients {
+        /// Gateway port
+        #[arg(short, long, default_value = "18790")]
+        port: u16,
+    },
+    /// Close a connected client's connection
+    Disconnect {
+        /// Connection ID (a unique prefix is enough) or client name
+        client: String,
+        /// Gateway port
+        #[arg(short, long, default_value = "18790")]
+        port: u16,
+    },
+}
+
 #[derive(clap::Subcommand, Debug)]
 pub enum UpdateAction {
     /// Check for available updates
-    Check,
-    /// Download and install the latest version
-    Install,
+    Check {
+        /// Release channel to check (stable, beta, nightly); defaults to config
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Download, verify, and install the latest version
+    Install {
+        /// Release channel to install from (stable, beta, nightly); defaults to config
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Restore the binary replaced by the last install
+    Rollback,
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -272,6 +598,22 @@ pub enum CronAction {
         /// Job name
         name: String,
     },
+    /// Stop a cron job from running, from any process, until released
+    Kill {
+        /// Job name
+        name: String,
+        /// Let the job run again
+        #[arg(long)]
+        release: bool,
+    },
+    /// Show recent runs of a cron job
+    History {
+        /// Job name
+        name: String,
+        /// Number of runs to show
+        #[arg(short = 'n', long, default_value = "10")]
+        limit: usize,
+    },
     /// List background jobs
     Jobs,
     /// Cancel a background job
@@ -331,6 +673,31 @@ pub enum ConfigAction {
     Init,
     /// Show current configuration
     Show,
+    /// Inspect or reset adaptive trust scores
+    Trust {
+        #[command(subcommand)]
+        action: TrustAction,
+    },
+    /// Re-encrypt this workspace's sessions under a new session key
+    RotateSessionKey,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum TrustAction {
+    /// List trust scores per tool and workspace with the events that moved them most
+    Show {
+        /// Only show this tool
+        #[arg(long, conflicts_with = "workspace")]
+        tool: Option<String>,
+        /// Only show this workspace path
+        #[arg(long)]
+        workspace: Option<String>,
+    },
+    /// Forget trust: a tool name, `workspace [path]` (default: current), or `all`
+    Reset {
+        #[arg(required = true, num_args = 1..=2)]
+        target: Vec<String>,
+    },
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -339,7 +706,7 @@ pub enum ChannelAction {
     List,
     /// Interactive channel setup wizard
     Setup {
-        /// Channel to configure (slack, discord, telegram, email, sms, imessage). Omit for menu.
+        /// Channel to configure (slack, discord, telegram, email, sms, signal, imessage). Omit for menu.
         channel: Option<String>,
     },
     /// Test a channel's connection (connect + disconnect)
@@ -352,6 +719,24 @@ pub enum ChannelAction {
         #[command(subcommand)]
         action: SlackCommand,
     },
+    /// Dry-run the routing rules for a message and show how it would be routed
+    RouteTest {
+        /// Channel type (e.g., slack, telegram, email)
+        #[arg(long)]
+        channel: String,
+        /// Sender ID or display name
+        #[arg(long)]
+        from: String,
+        /// Message text
+        #[arg(long)]
+        text: String,
+        /// Channel name or ID the message arrived in (e.g., #incidents)
+        #[arg(long)]
+        channel_name: Option<String>,
+        /// Local time to evaluate time windows at (HH:MM, default: now)
+        #[arg(long)]
+        at: Option<String>,
+    },
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -446,6 +831,42 @@ pub enum AuthAction {
         /// Provider name (e.g., openai, gemini, slack, discord, teams, whatsapp)
         provider: String,
     },
+    /// Move stored credentials between backends (env, keyring, file)
+    Migrate {
+        /// Where credentials are now: env, keyring, or file
+        #[arg(long)]
+        from: String,
+
+        /// Where to move them: keyring or file
+        #[arg(long)]
+        to: String,
+
+        /// Leave credentials in the source store after copying
+        #[arg(long)]
+        keep_source: bool,
+
+        /// List what would be moved without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Ask for the credential file passphrase on an interactive terminal.
+fn prompt_credential_passphrase(path: &std::path::Path, creating: bool) -> Option<String> {
+    use std::io::IsTerminal;
+    if !std::io::stdin().is_terminal() {
+        return None;
+    }
+    let prompt = if creating {
+        format!("New passphrase for {}", path.display())
+    } else {
+        format!("Passphrase for {}", path.display())
+    };
+    let mut password = dialoguer::Password::new().with_prompt(prompt);
+    if creating {
+        password = password.with_confirmation("Confirm passphrase", "Passphrases do not match");
+    }
+    password.interact().ok()
 }
 
 #[tokio::main]
@@ -455,21 +876,52 @@ async fn main() -> anyhow::Result<()> {
 
     let cli = Cli::parse();
 
+    // Unlock the encrypted credential file interactively when no key file or
+    // passphrase is set in the environment.
+    rustant_core::credentials::set_passphrase_prompt(prompt_credential_passphrase);
+
+    // Resolve workspace
+    let workspace = match &cli.workspace_name {
+        Some(name) => {
+            let registry_path = rustant_core::workspaces::default_workspaces_path()
+                .ok_or_else(|| anyhow::anyhow!("Could not determine the rustant data directory"))?;
+            rustant_core::WorkspaceRegistry::load(&registry_path)?.resolve(name)?
+        }
+        None => cli
+            .workspace
+            .canonicalize()
+            .unwrap_or_else(|_| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))),
+    };
+
+    let file_config = rustant_core::config::load_config(Some(&workspace), None).ok();
+
     // Set up tracing: human-readable stderr + JSON file logging
-    // Default to "warn" for clean output (hides INFO tool execution noise).
-    // Use -v for debug, -vv for trace, -q for errors only.
+    // Default to "warn" (or [logging] filter) for clean output (hides INFO
+    // tool execution noise). Use -v for debug, -vv for trace, -q for errors only.
+    let configured_filter = file_config
+        .as_ref()
+        .and_then(|c| c.logging.as_ref())
+        .and_then(|l| l.filter.clone());
     let filter = match cli.verbose {
-        0 if cli.quiet => "error",
-        0 => "warn",
-        1 => "info",
-        2 => "debug",
-        _ => "trace",
+        0 if cli.quiet => "error".to_string(),
+        0 => configured_filter.unwrap_or_else(|| "warn".to_string()),
+        1 => "info".to_string(),
+        2 => "debug".to_string(),
+        _ => "trace".to_string(),
     };
 
     // Human-readable layer for stderr (always active)
+    let (stderr_filter, stderr_filter_handle) = reload::Layer::new(EnvFilter::new(filter));
+    if cli.verbose == 0 && !cli.quiet {
+        let _ = STDERR_FILTER.set(Box::new(move |filter: EnvFilter| {
+            stderr_filter_handle
+                .reload(filter)
+                .map_err(|e| e.to_string())
+        }));
+    }
     let stderr_layer = tracing_subscriber::fmt::layer()
         .with_target(false)
-        .with_filter(EnvFilter::new(filter));
+        .with_filter(stderr_filter);
 
     // JSON file layer for structured logging
     let log_dir = directories::ProjectDirs::from("dev", "rustant", "rustant")
@@ -483,16 +935,41 @@ async fn main() -> anyhow::Result<()> {
         .with_writer(non_blocking)
         .with_filter(EnvFilter::new("debug"));
 
+    // Local event store from [event_store]; queued events are written when
+    // the guard drops at the end of main.
+    let mut event_store_error = None;
+    let _event_store = file_config.as_ref().and_then(|config| {
+        rustant_core::event_store::install(&config.event_store, &workspace).unwrap_or_else(|e| {
+            event_store_error = Some(e);
+            None
+        })
+    });
+
+    // OpenTelemetry export from [telemetry], shared by one-shot tasks, the
+    // REPL/TUI and every subcommand. Kept alive until main returns.
+    let mut telemetry_error = None;
+    let telemetry = file_config
+        .and_then(|config| config.telemetry)
+        .and_then(|config| {
+            let store = rustant_core::credentials::configured_store();
+            rustant_core::telemetry::Telemetry::init(&config, &store).unwrap_or_else(|e| {
+                telemetry_error = Some(e);
+                None
+            })
+        });
+
     tracing_subscriber::registry()
+        .with(telemetry.as_ref().map(|t| t.tracing_layer()))
         .with(stderr_layer)
         .with(json_layer)
         .init();
 
-    // Resolve workspace
-    let workspace = cli
-        .workspace
-        .canonicalize()
-        .unwrap_or_else(|_| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+    if let Some(e) = telemetry_error {
+        tracing::warn!("Telemetry export disabled: {}", e);
+    }
+    if let Some(e) = event_store_error {
+        tracing::warn!("Event store disabled: {}", e);
+    }
 
     // Handle subcommands
     if let Some(command) = cli.command {
@@ -553,6 +1030,53 @@ async fn main() -> anyhow::Result<()> {
         };
         approval_source = "CLI --approval flag";
     }
+    if let Some(name) = &cli.persona {
+        config.persona = Some(name.clone());
+    }
+    if let Some(name) = &config.persona {
+        let registry = rustant_core::PersonaRegistry::from_config(&config.personas);
+        let persona = registry.get(name)?;
+        if cli.model.is_none()
+            && let Some(model) = &persona.model
+        {
+            config.llm.model = model.clone();
+        }
+    }
+
+    // Past the workspace's monthly soft cap, warn and switch to the cheaper
+    // `[budget] over_cap_model` unless a model was chosen on the command line.
+    if let Some(cap) = rustant_core::event_store::CostCap::from_config(&config, &workspace) {
+        match cap.status(chrono::Utc::now()) {
+            Ok(status) if status.exceeded() => {
+                eprintln!("  \x1b[33m⚠ {}\x1b[0m", status.warning());
+                let cheaper = config
+                    .budget
+                    .as_ref()
+                    .and_then(|budget| budget.over_cap_model.clone());
+                if let Some(model) = cheaper
+                    && !cli.ignore_cost_cap
+                    && cli.model.is_none()
+                {
+                    eprintln!(
+                        "  \x1b[33m  Using {} instead of {} for the rest of {} \
+                         (--ignore-cost-cap keeps it).\x1b[0m",
+                        model, config.llm.model, status.month
+                    );
+                    config.llm.model = model;
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Monthly cost unavailable: {}", e),
+        }
+    }
+
+    // Refuse to start with tool contracts that do not validate.
+    rustant_core::ContractSet::load(&config.contracts, &workspace).map_err(|e| {
+        anyhow::anyhow!(
+            "Tool contract error: {} (check with `rustant policy validate`)",
+            e
+        )
+    })?;
 
     // Always show approval mode when non-default (helps diagnose env var issues)
     if config.safety.approval_mode != rustant_core::ApprovalMode::Safe {
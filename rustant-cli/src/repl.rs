@@ -1,9 +1,11 @@
 //! REPL (Read-Eval-Print Loop) for interactive and single-task modes.
 
+use crate::repl_markdown::StreamRenderer;
 #[cfg(feature = "browser")]
 use rustant_core::browser::BrowserSecurityGuard;
 use rustant_core::browser::CdpClient;
 use rustant_core::explanation::DecisionExplanation;
+use rustant_core::pins::PinTarget;
 use rustant_core::safety::{ActionRequest, ApprovalDecision};
 #[cfg(feature = "browser")]
 use rustant_core::types::ToolDefinition;
@@ -17,8 +19,8 @@ use rustant_tools::register_builtin_tools;
 use rustant_tools::registry::ToolRegistry;
 use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 /// Connect to or launch a browser and register all 24 browser tools with the agent.
 ///
@@ -58,7 +60,7 @@ async fn try_register_browser_tools(
                     browser_config.blocked_domains.clone(),
                 ));
                 let ctx = BrowserToolContext::new(Arc::clone(&client), security);
-                register_browser_tools_to_agent(agent, ctx);
+                register_browser_tools_to_agent(agent, ctx, &config.tools.web_fetch, workspace);
                 println!(
                     "\x1b[90m  Browser: reconnected ({} tabs, port {})\x1b[0m",
                     tab_count, saved.debug_port
@@ -84,7 +86,7 @@ async fn try_register_browser_tools(
                     browser_config.blocked_domains.clone(),
                 ));
                 let ctx = BrowserToolContext::new(Arc::clone(&client), security);
-                register_browser_tools_to_agent(agent, ctx);
+                register_browser_tools_to_agent(agent, ctx, &config.tools.web_fetch, workspace);
 
                 // Save session for future reconnection
                 let info = BrowserConnectionInfo {
@@ -100,7 +102,7 @@ async fn try_register_browser_tools(
                 }
 
                 println!(
-                    "\x1b[90m  Browser automation: 24 tools registered ({}, {} tabs)\x1b[0m",
+                    "\x1b[90m  Browser automation: 25 tools registered ({}, {} tabs)\x1b[0m",
                     mode, tab_count
                 );
                 return Some(client);
@@ -124,9 +126,29 @@ async fn try_register_browser_tools(
 ///
 /// This converts each `Arc<dyn Tool>` from `create_browser_tools()` into a
 /// `RegisteredTool` with the proper `ToolDefinition`, `RiskLevel`, and executor.
+/// With `[tools.web_fetch] js_render` on, `web_fetch` is replaced by one that
+/// renders near-empty pages in this browser session.
 #[cfg(feature = "browser")]
-fn register_browser_tools_to_agent(agent: &mut Agent, ctx: BrowserToolContext) {
-    let tools = create_browser_tools(ctx);
+fn register_browser_tools_to_agent(
+    agent: &mut Agent,
+    ctx: BrowserToolContext,
+    web_fetch: &rustant_core::config::WebFetchConfig,
+    workspace: &Path,
+) {
+    let mut tools = create_browser_tools(ctx.clone());
+    tools.push(Arc::new(
+        rustant_tools::reader_capture::BrowserCaptureTool::new(
+            ctx.clone(),
+            workspace.to_path_buf(),
+        ),
+    ));
+    if web_fetch.js_render {
+        tools.push(Arc::new(
+            rustant_tools::web::WebFetchTool::new()
+                .with_config(web_fetch.clone())
+                .with_browser(ctx),
+        ));
+    }
     for tool in tools {
         let name = tool.name().to_string();
         let description = tool.description().to_string();
@@ -139,6 +161,12 @@ fn register_browser_tools_to_agent(agent: &mut Agent, ctx: BrowserToolContext) {
             parameters,
         };
 
+        let summarizer = Arc::clone(&tool);
+        agent.register_tool_summarizer(
+            name.clone(),
+            Box::new(move |args| summarizer.summarize_args(args)),
+        );
+
         // Create an executor closure that calls the Arc<dyn Tool>::execute
         let tool_arc = tool;
         let executor: rustant_core::agent::ToolExecutor = Box::new(move |args| {
@@ -154,6 +182,23 @@ fn register_browser_tools_to_agent(agent: &mut Agent, ctx: BrowserToolContext) {
     }
 }
 
+/// Prompt prefix shown while the workspace is over its `[budget]
+/// monthly_soft_cap_usd`; empty otherwise.
+fn cost_cap_marker(config: &AgentConfig, workspace: &Path) -> String {
+    let Some(cap) = rustant_core::event_store::CostCap::from_config(config, workspace) else {
+        return String::new();
+    };
+    // Count the task that just finished.
+    rustant_core::event_store::flush(std::time::Duration::from_millis(500));
+    match cap.status(chrono::Utc::now()) {
+        Ok(status) if status.exceeded() => format!(
+            "\x1b[33m[over cap ${:.2}/${:.2}]\x1b[0m ",
+            status.spent_usd, status.cap_usd
+        ),
+        _ => String::new(),
+    }
+}
+
 /// Truncate a string to at most `max_chars` characters, respecting UTF-8 boundaries.
 fn truncate_str(s: &str, max_chars: usize) -> &str {
     match s.char_indices().nth(max_chars) {
@@ -428,6 +473,50 @@ pub(crate) fn extract_tool_detail(tool_name: &str, args: &serde_json::Value) ->
                 action.to_string()
             })
         }
+        "citation_archive" => {
+            let action = args
+                .get("action")
+                .and_then(|v| v.as_str())
+                .unwrap_or("list");
+            let detail = args
+                .get("url")
+                .or_else(|| args.get("name"))
+                .and_then(|v| v.as_str());
+            Some(if let Some(d) = detail {
+                format!("{}: {}", action, d)
+            } else {
+                action.to_string()
+            })
+        }
+        "model_registry" => {
+            let action = args
+                .get("action")
+                .and_then(|v| v.as_str())
+                .unwrap_or("list");
+            let detail = args.get("name").and_then(|v| v.as_str());
+            let stage = args.get("stage").and_then(|v| v.as_str());
+            Some(match (detail, stage) {
+                (Some(d), Some(s)) => format!("{}: {} → {}", action, d, s),
+                (Some(d), None) => format!("{}: {}", action, d),
+                _ => action.to_string(),
+            })
+        }
+        "incident" => {
+            let action = args
+                .get("action")
+                .and_then(|v| v.as_str())
+                .unwrap_or("status");
+            let detail = args
+                .get("title")
+                .or_else(|| args.get("step_id"))
+                .or_else(|| args.get("severity"))
+                .and_then(|v| v.as_str());
+            Some(if let Some(d) = detail {
+                format!("{}: {}", action, d)
+            } else {
+                action.to_string()
+            })
+        }
         "life_planner" => {
             let action = args
                 .get("action")
@@ -484,23 +573,103 @@ pub(crate) fn extract_tool_detail(tool_name: &str, args: &serde_json::Value) ->
 pub(crate) struct CliCallback {
     pub verbose: Arc<AtomicBool>,
     has_streamed: Arc<AtomicBool>,
+    /// Markdown renderer for the response being streamed.
+    markdown: Mutex<Option<StreamRenderer>>,
+    /// Voice session state; approvals for spoken requests are asked by voice.
+    toggle_state: Option<Arc<rustant_core::ToggleState>>,
+    /// The tool call in progress.
+    running: Mutex<Option<RunningCall>>,
+}
+
+/// A running tool call. On a terminal its progress line is redrawn in
+/// place with the elapsed time: always once the call is slow, and from the
+/// start in verbose mode.
+struct RunningCall {
+    summary: String,
+    slow: Arc<AtomicBool>,
+    ticker: Option<tokio::task::JoinHandle<()>>,
 }
 
+/// Spinner frames for a slow call's progress line.
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
 impl CliCallback {
     pub fn new(verbose: bool) -> Self {
         Self {
             verbose: Arc::new(AtomicBool::new(verbose)),
             has_streamed: Arc::new(AtomicBool::new(false)),
+            markdown: Mutex::new(None),
+            toggle_state: None,
+            running: Mutex::new(None),
+        }
+    }
+
+    /// Ask for approval by voice while a voice turn is being handled.
+    pub fn with_toggle_state(mut self, toggle_state: Arc<rustant_core::ToggleState>) -> Self {
+        self.toggle_state = Some(toggle_state);
+        self
+    }
+
+    /// Stop redrawing the running call's progress line and clear it.
+    /// Returns the call, which stays running.
+    fn stop_ticker(&self) -> Option<String> {
+        let mut running = self.running.lock().ok()?;
+        let call = running.as_mut()?;
+        if let Some(ticker) = call.ticker.take() {
+            ticker.abort();
+            if self.verbose.load(Ordering::Relaxed) || call.slow.load(Ordering::Relaxed) {
+                print!("\r\x1b[2K");
+                let _ = io::stdout().flush();
+            }
         }
+        Some(call.summary.clone())
     }
 }
 
+/// Redraw a running call's progress line every 200ms until aborted.
+fn spawn_call_ticker(
+    summary: String,
+    verbose: bool,
+    slow: Arc<AtomicBool>,
+) -> tokio::task::JoinHandle<()> {
+    let started = std::time::Instant::now();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_millis(200));
+        for frame in SPINNER_FRAMES.iter().cycle() {
+            interval.tick().await;
+            let elapsed = started.elapsed().as_secs_f64();
+            if slow.load(Ordering::Relaxed) {
+                print!(
+                    "\r\x1b[2K\x1b[33m  {} [{}] {:.0}s — Ctrl-C cancels this call\x1b[0m",
+                    frame, summary, elapsed
+                );
+            } else if verbose {
+                print!(
+                    "\r\x1b[2K\x1b[36m  [{}] running {:.1}s\x1b[0m",
+                    summary, elapsed
+                );
+            } else {
+                continue;
+            }
+            let _ = io::stdout().flush();
+        }
+    })
+}
+
 #[async_trait::async_trait]
 impl AgentCallback for CliCallback {
     async fn on_assistant_message(&self, message: &str) {
         if self.has_streamed.swap(false, Ordering::SeqCst) {
-            // Streaming tokens already displayed this content — just terminate the line.
-            println!();
+            // Streaming tokens already displayed this content — print what the
+            // renderer still holds and terminate the line.
+            let rest = self
+                .markdown
+                .lock()
+                .ok()
+                .and_then(|mut renderer| renderer.take())
+                .map(|mut renderer| renderer.finish())
+                .unwrap_or_default();
+            println!("{}", rest);
         } else {
             println!("\n\x1b[32mRustant:\x1b[0m {}", message);
         }
@@ -508,16 +677,35 @@ impl AgentCallback for CliCallback {
 
     async fn on_token(&self, token: &str) {
         self.has_streamed.store(true, Ordering::SeqCst);
-        print!("{}", token);
+        let rendered = match self.markdown.lock() {
+            Ok(mut renderer) => renderer
+                .get_or_insert_with(StreamRenderer::for_stdout)
+                .push(token),
+            Err(_) => token.to_string(),
+        };
+        print!("{}", rendered);
         let _ = io::stdout().flush();
     }
 
     async fn request_approval(&self, action: &ActionRequest) -> ApprovalDecision {
+        // Keep a running call's progress line off the prompt.
+        self.stop_ticker();
         println!(
             "\n\x1b[33m[Approval Required]\x1b[0m {} (risk: {})",
             action.description, action.risk_level
         );
 
+        if let Some(ref toggle_state) = self.toggle_state
+            && let Some(decision) = toggle_state.voice_approval(action).await
+        {
+            if decision == ApprovalDecision::Approve {
+                println!("  \x1b[32m[Voice] Confirmed\x1b[0m");
+            } else {
+                println!("  \x1b[31m[Voice] Not confirmed — denied\x1b[0m");
+            }
+            return decision;
+        }
+
         // Show rich context if available
         if let Some(ref reasoning) = action.approval_context.reasoning {
             println!("  \x1b[90mReason:\x1b[0m {}", reasoning);
@@ -557,19 +745,57 @@ impl AgentCallback for CliCallback {
         }
     }
 
-    async fn on_tool_start(&self, tool_name: &str, args: &serde_json::Value) {
-        if !self.verbose.load(Ordering::Relaxed) {
-            return;
-        }
-        let detail = extract_tool_detail(tool_name, args);
-        if let Some(ref detail) = detail {
-            println!("\x1b[36m  [{}: {}] executing...\x1b[0m", tool_name, detail);
+    async fn on_tool_start(&self, _tool_name: &str, _args: &serde_json::Value) {
+        // The running call is shown from its summary in on_tool_call_started.
+    }
+
+    async fn on_tool_call_started(&self, _tool_name: &str, summary: &str) {
+        use std::io::IsTerminal;
+        self.stop_ticker();
+        let verbose = self.verbose.load(Ordering::Relaxed);
+        let slow = Arc::new(AtomicBool::new(false));
+        let ticker = if io::stdout().is_terminal() {
+            Some(spawn_call_ticker(
+                summary.to_string(),
+                verbose,
+                slow.clone(),
+            ))
         } else {
-            println!("\x1b[36m  [{}] executing...\x1b[0m", tool_name);
+            if verbose {
+                println!("\x1b[36m  [{}] running...\x1b[0m", summary);
+            }
+            None
+        };
+        if let Ok(mut running) = self.running.lock() {
+            *running = Some(RunningCall {
+                summary: summary.to_string(),
+                slow,
+                ticker,
+            });
+        }
+    }
+
+    async fn on_tool_call_slow(&self, _tool_name: &str, summary: &str) {
+        let live = match self.running.lock() {
+            Ok(running) => running.as_ref().is_some_and(|call| {
+                call.slow.store(true, Ordering::Relaxed);
+                call.ticker.is_some()
+            }),
+            Err(_) => false,
+        };
+        if !live {
+            println!(
+                "\x1b[33m  [{}] still running — Ctrl-C cancels this call\x1b[0m",
+                summary
+            );
         }
     }
 
     async fn on_tool_result(&self, tool_name: &str, output: &ToolOutput, duration_ms: u64) {
+        let summary = self.stop_ticker();
+        if let Ok(mut running) = self.running.lock() {
+            *running = None;
+        }
         if !self.verbose.load(Ordering::Relaxed) {
             return;
         }
@@ -580,7 +806,9 @@ impl AgentCallback for CliCallback {
         };
         println!(
             "\x1b[36m  [{}] completed in {}ms\x1b[0m\n  {}",
-            tool_name, duration_ms, preview
+            summary.as_deref().unwrap_or(tool_name),
+            duration_ms,
+            preview
         );
     }
 
@@ -794,6 +1022,44 @@ impl AgentCallback for CliCallback {
         }
     }
 
+    async fn on_cost_preflight(
+        &self,
+        estimate: &rustant_core::cost_preflight::PlanEstimate,
+        confirm: bool,
+    ) -> rustant_core::cost_preflight::CostDecision {
+        use rustant_core::cost_preflight::CostDecision;
+
+        if !confirm {
+            println!("\x1b[90m[Estimate] {}\x1b[0m", estimate.summary());
+            return CostDecision::Proceed;
+        }
+        println!(
+            "\x1b[33mEstimated cost: {}\x1b[0m \x1b[1m[c]\x1b[0monfirm / \x1b[1m[a]\x1b[0mdjust plan / \x1b[1m[x]\x1b[0m cancel",
+            estimate.summary()
+        );
+        print!("> ");
+        let _ = io::stdout().flush();
+
+        let mut input = String::new();
+        if io::stdin().lock().read_line(&mut input).is_err() {
+            return CostDecision::Cancel;
+        }
+        match input.trim().chars().next() {
+            None | Some('c') | Some('y') => CostDecision::Proceed,
+            Some('a') | Some('e') => CostDecision::Adjust,
+            _ => CostDecision::Cancel,
+        }
+    }
+
+    async fn on_cost_report(&self, report: &rustant_core::cost_preflight::CostReport) {
+        println!("\x1b[90m{}\x1b[0m", report.render());
+    }
+
+    async fn on_task_resume(&self, report: &rustant_core::task_checkpoint::ResumeReport) {
+        let color = if report.drift.is_empty() { "90" } else { "33" };
+        println!("\x1b[{}m{}\x1b[0m", color, report.render());
+    }
+
     async fn on_plan_step_start(&self, step_index: usize, step: &rustant_core::plan::PlanStep) {
         let tool_info = step
             .tool
@@ -833,7 +1099,7 @@ fn print_contextual_hints(agent: &Agent) {
     }
 
     let context_window = agent.brain().context_window();
-    let ctx = mem.context_breakdown(context_window);
+    let ctx = mem.context_breakdown_with(context_window, agent.brain().token_counter());
     if ctx.usage_ratio() > 0.7 {
         hints.push(
             "Tip: Context is >70% full. Use /pin to protect important messages before compression.",
@@ -911,7 +1177,7 @@ fn show_onboarding(workspace: &Path) {
 }
 
 /// Run the agent in interactive REPL mode.
-pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow::Result<()> {
+pub async fn run_interactive(config: AgentConfig, mut workspace: PathBuf) -> anyhow::Result<()> {
     println!("\x1b[1;32m");
     println!(r#"  ██████╗ ██╗   ██╗███████╗████████╗ █████╗ ███╗   ██╗████████╗"#);
     println!(r#"  ██╔══██╗██║   ██║██╔════╝╚══██╔══╝██╔══██╗████╗  ██║╚══██╔══╝"#);
@@ -932,7 +1198,7 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
     show_onboarding(&workspace);
 
     let provider = if config.llm.auth_method == "oauth" {
-        let cred_store = rustant_core::credentials::KeyringCredentialStore::new();
+        let cred_store = rustant_core::credentials::configured_store();
         match rustant_core::create_provider_with_auth(&config.llm, &cred_store).await {
             Ok(p) => p,
             Err(e) => {
@@ -949,11 +1215,20 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
             }
         }
     };
-    let callback = Arc::new(CliCallback::new(config.ui.verbose));
+    rustant_tools::reader_capture::set_topic_tagger(Arc::clone(&provider));
+    rustant_tools::lsp::pre_write::install_pre_write_check(
+        &config.tools.pre_write_diagnostics,
+        &workspace,
+    );
+    // Create shared toggle state for voice/meeting toggles
+    let toggle_state = rustant_core::ToggleState::new();
+    let callback =
+        Arc::new(CliCallback::new(config.ui.verbose).with_toggle_state(toggle_state.clone()));
     let verbose_flag = Arc::clone(&callback.verbose);
     // Clone config before moving into Agent (needed for browser setup)
     let config_ref = config.clone();
     let mut agent = Agent::new(provider, config, callback);
+    agent.set_vision_provider(rustant_core::create_vision_provider(&config_ref.llm));
 
     // Register built-in tools as agent tools
     let mut registry = ToolRegistry::new();
@@ -964,15 +1239,20 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
     // Keep _browser_client alive so Chrome stays open for the REPL session.
     let _browser_client = try_register_browser_tools(&mut agent, &config_ref, &workspace).await;
 
-    // Create shared toggle state for voice/meeting toggles
-    let toggle_state = rustant_core::ToggleState::new();
-
     // Load scheduler state from disk
     let scheduler_state_dir = workspace.join(".rustant").join("scheduler");
     agent.load_scheduler_state(&scheduler_state_dir);
+    if let Some(trust_path) = rustant_core::trust::default_trust_path() {
+        agent.load_trust_store(&trust_path);
+    }
+
+    // A focus block cut short by a crash must not leave Do Not Disturb on.
+    if let Some(notice) = rustant_tools::pomodoro::reconcile_focus(&workspace).await {
+        println!("\x1b[90m  {}\x1b[0m", notice);
+    }
 
     // Attempt auto-recovery of the most recent session
-    if let Ok(mut mgr) = rustant_core::SessionManager::new(&workspace) {
+    if let Ok(mut mgr) = crate::commands::open_sessions(&workspace) {
         match mgr.resume_latest() {
             Ok((memory, _continuation)) => {
                 let msg_count = memory.short_term.len();
@@ -992,18 +1272,27 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
 
     // Set up signal handling with double-tap Ctrl+C support:
     // First Ctrl+C cancels the current task, second within 2s exits.
+    // While a tool call runs past `[tools] slow_call_secs`, Ctrl+C cancels
+    // just that call and the task carries on.
     let interrupt_count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
     let shared_cancel_token: Arc<tokio::sync::Mutex<CancellationToken>> =
         Arc::new(tokio::sync::Mutex::new(agent.cancellation_token()));
     {
         let interrupt_count = interrupt_count.clone();
         let shared_cancel_token = shared_cancel_token.clone();
+        let call_canceller = agent.slow_call_canceller();
         let ws_for_signal = workspace.clone();
         tokio::spawn(async move {
             loop {
                 if tokio::signal::ctrl_c().await.is_err() {
                     break;
                 }
+                if interrupt_count.load(std::sync::atomic::Ordering::SeqCst) == 0
+                    && call_canceller.cancel_slow_call()
+                {
+                    eprintln!("\nCancelled the slow tool call; the task continues.");
+                    continue;
+                }
                 let count = interrupt_count.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
                 if count == 1 {
                     // First Ctrl+C: cancel the current task
@@ -1026,21 +1315,57 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
         });
     }
 
-    let cmd_registry = crate::slash::CommandRegistry::with_defaults();
+    let mut cmd_registry = crate::slash::CommandRegistry::with_defaults();
+    match rustant_core::CustomCommandSet::load(&config_ref.commands, &workspace)
+        .and_then(|custom| cmd_registry.register_custom(custom))
+    {
+        Ok(()) => {}
+        Err(e) => println!("\x1b[31mCustom commands not loaded: {}\x1b[0m", e),
+    }
     let mut repl_input = crate::repl_input::ReplInput::new(&workspace);
     loop {
-        let input = match repl_input.read_line("\x1b[1;34m> \x1b[0m", &cmd_registry) {
+        let prompt = match agent.active_persona() {
+            Some(p) => format!("\x1b[1;34m[{}] > \x1b[0m", p.name),
+            None => "\x1b[1;34m> \x1b[0m".to_string(),
+        };
+        let prompt = format!("{}{}", cost_cap_marker(&config_ref, &workspace), prompt);
+        let input = match repl_input.read_line(&prompt, &cmd_registry) {
             Ok(Some(line)) => line,
             Ok(None) => break, // Ctrl-D EOF
             Err(_) => break,
         };
+        // Set when the line was spoken; the agent's reply is read back.
+        let voice_turn = repl_input.take_voice_turn();
 
         let input = input.trim();
         if input.is_empty() {
             continue;
         }
 
+        // Terminals paste a file dragged onto them as its path; attach
+        // dropped images to the message instead of sending their paths.
+        let first_word = input.split_whitespace().next().unwrap_or("");
+        let remaining;
+        let input = if cmd_registry.lookup(first_word).is_none() {
+            let (text, paths) = rustant_core::attachments::take_dropped_images(input);
+            for path in &paths {
+                report_attached_image(agent.attach_image(path), &agent);
+            }
+            remaining = text;
+            if paths.is_empty() {
+                input
+            } else if remaining.is_empty() {
+                println!("\x1b[90m  Type your message; the image goes with it.\x1b[0m");
+                continue;
+            } else {
+                remaining.as_str()
+            }
+        } else {
+            input
+        };
+
         // Handle commands
+        let mut expanded: Option<String> = None;
         if input.starts_with('/') {
             let parts: Vec<&str> = input.splitn(3, ' ').collect();
             let cmd = parts[0];
@@ -1049,6 +1374,9 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
 
             match cmd {
                 "/quit" | "/exit" | "/q" => {
+                    if !agent.distill_session().await.is_empty() {
+                        print_learned(&agent);
+                    }
                     // Offer to save session if there's meaningful context
                     if agent.memory().short_term.total_messages_seen() > 2 {
                         print!("Save session before exiting? [y/n/name] > ");
@@ -1062,8 +1390,21 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                                 } else {
                                     Some(save_input)
                                 };
-                                if let Ok(mut mgr) = rustant_core::SessionManager::new(&workspace) {
+                                if let Ok(mut mgr) = crate::commands::open_sessions(&workspace) {
                                     let entry = mgr.start_session(session_name);
+                                    let persona = agent.active_persona().map(|p| p.name.as_str());
+                                    if let Err(e) = mgr.set_active_persona(persona) {
+                                        tracing::warn!("Failed to record persona: {}", e);
+                                    }
+                                    if let Err(e) = mgr.set_artifacts(agent.artifacts()) {
+                                        tracing::warn!("Failed to record artifacts: {}", e);
+                                    }
+                                    if let Err(e) = mgr.set_task_runs(agent.task_runs()) {
+                                        tracing::warn!("Failed to record task runs: {}", e);
+                                    }
+                                    if let Err(e) = mgr.save_recording(agent.recording()) {
+                                        tracing::warn!("Failed to save session recording: {}", e);
+                                    }
                                     let total_tokens = agent.brain().total_usage().total();
                                     match mgr.save_checkpoint(agent.memory(), total_tokens) {
                                         Ok(()) => {
@@ -1120,6 +1461,10 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                         usage.total()
                     );
                     println!("Cost: ${:.4}", cost.total());
+                    if let Some(cache) = agent.brain().response_cache() {
+                        println!("Cache (task): {}", agent.brain().cache_task_stats());
+                        println!("Cache (overall): {}", cache.stats());
+                    }
                     continue;
                 }
                 "/tools" => {
@@ -1148,10 +1493,39 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                     handle_resume_command(arg1, &mut agent, &workspace);
                     continue;
                 }
+                "/continue" => {
+                    agent.reset_cancellation();
+                    *shared_cancel_token.lock().await = agent.cancellation_token();
+                    interrupt_count.store(0, std::sync::atomic::Ordering::SeqCst);
+                    match agent.continue_task().await {
+                        Ok(result) => println!(
+                            "\x1b[90m  [{} iterations, {} tokens, ${:.4}]\x1b[0m",
+                            result.iterations,
+                            result.total_usage.total(),
+                            result.total_cost.total()
+                        ),
+                        Err(e) => println!("\x1b[31mError: {}\x1b[0m", e),
+                    }
+                    continue;
+                }
                 "/sessions" => {
                     handle_sessions_command(arg1, arg2, &workspace);
                     continue;
                 }
+                "/fork" => {
+                    handle_fork_command(arg1, arg2, &mut agent, &workspace);
+                    continue;
+                }
+                "/compare" => {
+                    handle_compare_command(arg1, arg2, &workspace);
+                    continue;
+                }
+                "/workspace" => {
+                    if let Some(target) = handle_workspace_command(arg1, &mut agent, &workspace) {
+                        workspace = target;
+                    }
+                    continue;
+                }
                 "/safety" => {
                     handle_safety_command(&agent);
                     continue;
@@ -1160,12 +1534,32 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                     handle_memory_command(&agent);
                     continue;
                 }
+                "/learned" => {
+                    handle_learned_command(arg1, arg2, &mut agent);
+                    continue;
+                }
                 "/pin" => {
-                    handle_pin_command(arg1, &mut agent);
+                    let arg = input[cmd.len()..].trim();
+                    handle_pin_command(arg, &mut agent, &workspace);
                     continue;
                 }
                 "/unpin" => {
-                    handle_unpin_command(arg1, &mut agent);
+                    let arg = input[cmd.len()..].trim();
+                    handle_unpin_command(arg, &mut agent);
+                    continue;
+                }
+                "/pins" => {
+                    handle_pins_command(&agent);
+                    continue;
+                }
+                "/attach" => {
+                    let arg = input[cmd.len()..].trim();
+                    handle_attach_command(arg, &mut agent).await;
+                    continue;
+                }
+                "/clip" => {
+                    let arg = input[cmd.len()..].trim();
+                    handle_clip_command(arg, &mut agent, &workspace);
                     continue;
                 }
                 "/context" => {
@@ -1197,7 +1591,7 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                     continue;
                 }
                 "/trust" => {
-                    handle_trust_command(&agent);
+                    handle_trust_command(arg1, arg2, &mut agent);
                     continue;
                 }
                 "/keys" => {
@@ -1286,6 +1680,14 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                     handle_why_command(arg1, &agent);
                     continue;
                 }
+                "/persona" => {
+                    handle_persona_command(arg1, &mut agent, &config_ref);
+                    continue;
+                }
+                "/artifacts" | "/art" => {
+                    println!("{}", artifacts_command(&mut agent, arg1, arg2));
+                    continue;
+                }
                 "/channel" | "/ch" => {
                     let action = match arg1 {
                         "list" | "" => crate::ChannelAction::List,
@@ -1491,7 +1893,26 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                 }
                 "/skill" => {
                     let action = match arg1 {
-                        "list" | "" => crate::SkillAction::List { dir: None },
+                        "list" | "" => crate::SkillAction::List {
+                            dir: None,
+                            offline: false,
+                        },
+                        "install" => {
+                            if arg2.is_empty() {
+                                println!("Usage: /skill install <source>");
+                                continue;
+                            }
+                            crate::SkillAction::Install {
+                                source: arg2.to_string(),
+                                yes: false,
+                                dir: None,
+                            }
+                        }
+                        "update" => crate::SkillAction::Update {
+                            name: (!arg2.is_empty()).then(|| arg2.to_string()),
+                            yes: false,
+                            dir: None,
+                        },
                         "info" => {
                             if arg2.is_empty() {
                                 println!("Usage: /skill info <path>");
@@ -1511,11 +1932,13 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                             }
                         }
                         _ => {
-                            println!("Usage: /skill list|info|validate <path>");
+                            println!(
+                                "Usage: /skill list|install <source>|update [name]|info|validate <path>"
+                            );
                             continue;
                         }
                     };
-                    if let Err(e) = crate::commands::handle_skill(action).await {
+                    if let Err(e) = crate::commands::handle_skill(action, &workspace).await {
                         println!("\x1b[31mError: {}\x1b[0m", e);
                     }
                     continue;
@@ -1544,14 +1967,15 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                 }
                 "/update" => {
                     let action = match arg1 {
-                        "check" | "" => crate::UpdateAction::Check,
-                        "install" => crate::UpdateAction::Install,
+                        "check" | "" => crate::UpdateAction::Check { channel: None },
+                        "install" => crate::UpdateAction::Install { channel: None },
+                        "rollback" => crate::UpdateAction::Rollback,
                         _ => {
-                            println!("Usage: /update check|install");
+                            println!("Usage: /update check|install|rollback");
                             continue;
                         }
                     };
-                    if let Err(e) = crate::commands::handle_update(action).await {
+                    if let Err(e) = crate::commands::handle_update(action, &workspace).await {
                         println!("\x1b[31mError: {}\x1b[0m", e);
                     }
                     continue;
@@ -1562,13 +1986,14 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                             let ts = toggle_state.clone();
                             let cfg = config_ref.clone();
                             let ws = workspace.clone();
-                            let on_text = Arc::new(|text: String| {
-                                println!("\n\x1b[36m  [Voice] \x1b[0m{}", text);
-                            });
-                            match ts.voice_start(cfg, ws, on_text).await {
-                                Ok(()) => println!(
-                                    "\x1b[32m  Voice command mode ON\x1b[0m — listening for speech..."
-                                ),
+                            let (turn_tx, turn_rx) = tokio::sync::mpsc::channel(1);
+                            match ts.voice_start(cfg, ws, turn_tx).await {
+                                Ok(()) => {
+                                    repl_input.set_voice_turns(turn_rx);
+                                    println!(
+                                        "\x1b[32m  Voice command mode ON\x1b[0m — listening for speech..."
+                                    )
+                                }
                                 Err(e) => println!("\x1b[31mError starting voice: {}\x1b[0m", e),
                             }
                         }
@@ -1612,6 +2037,11 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                                 Ok(result) => {
                                     println!("\x1b[32m  Meeting recording stopped\x1b[0m");
                                     println!("  Duration: {}s", result.duration_secs);
+                                    println!("  Transcription: {}", result.transcription_mode);
+                                    let speakers = result.speakers();
+                                    if !speakers.is_empty() {
+                                        println!("  Speakers: {}", speakers.join(", "));
+                                    }
                                     if !result.transcript.is_empty() {
                                         let preview = if result.transcript.len() > 200 {
                                             format!("{}...", &result.transcript[..200])
@@ -1621,7 +2051,7 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                                         println!("  Transcript preview: {}", preview);
                                     } else {
                                         println!(
-                                            "  No transcript available (check OPENAI_API_KEY)"
+                                            "  No transcript available (check OPENAI_API_KEY or [meeting] stt_provider)"
                                         );
                                     }
                                     if result.notes_saved {
@@ -1638,6 +2068,23 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                                     println!("  Title: {}", title);
                                 }
                                 println!("  Elapsed: {}s", status.elapsed_secs);
+                                if let Some(live) = &status.live_transcript {
+                                    println!("  Transcription: {}", live.mode);
+                                    for segment in live.recent(3) {
+                                        println!(
+                                            "  [{}] {}{}",
+                                            rustant_core::voice::streaming::format_timestamp(
+                                                segment.start_secs
+                                            ),
+                                            segment
+                                                .speaker
+                                                .as_deref()
+                                                .map(|s| format!("{}: ", s))
+                                                .unwrap_or_default(),
+                                            segment.text
+                                        );
+                                    }
+                                }
                             } else {
                                 println!("\x1b[90m  No active recording\x1b[0m");
                             }
@@ -1697,30 +2144,49 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
                     continue;
                 }
                 _ => {
-                    // Check if this is a TUI-only command
-                    if let Some(info) = cmd_registry.lookup(cmd)
-                        && info.tui_only
-                    {
-                        println!(
-                            "The {} command is only available in TUI mode. Launch with: rustant --tui",
-                            cmd
-                        );
-                        continue;
-                    }
-                    // Use registry for unknown command suggestions
-                    if let Some(suggestion) = cmd_registry.suggest(cmd) {
-                        println!("Unknown command: {}. Did you mean {}?", cmd, suggestion);
+                    if let Some(custom) = cmd_registry.custom_command(cmd) {
+                        let args = input[cmd.len()..].trim();
+                        let prompt = custom
+                            .check_approval_mode(agent.safety().approval_mode())
+                            .and_then(|()| custom.expand(args));
+                        match prompt {
+                            Ok(prompt) => {
+                                println!("\x1b[90m  {}\x1b[0m", prompt);
+                                expanded = Some(prompt);
+                            }
+                            Err(e) => {
+                                println!("\x1b[31m{}\x1b[0m", e);
+                                continue;
+                            }
+                        }
                     } else {
-                        println!(
-                            "Unknown command: {}. Type /help for available commands.",
-                            cmd
-                        );
+                        // Check if this is a TUI-only command
+                        if let Some(info) = cmd_registry.lookup(cmd)
+                            && info.tui_only
+                        {
+                            println!(
+                                "The {} command is only available in TUI mode. Launch with: rustant --tui",
+                                cmd
+                            );
+                            continue;
+                        }
+                        // Use registry for unknown command suggestions
+                        if let Some(suggestion) = cmd_registry.suggest(cmd) {
+                            println!("Unknown command: {}. Did you mean {}?", cmd, suggestion);
+                        } else {
+                            println!(
+                                "Unknown command: {}. Type /help for available commands.",
+                                cmd
+                            );
+                        }
+                        continue;
                     }
-                    continue;
                 }
             }
         }
 
+        let input = expanded.as_deref().unwrap_or(input);
+
         // Reset cancellation for the new task and update the shared token
         agent.reset_cancellation();
         *shared_cancel_token.lock().await = agent.cancellation_token();
@@ -1729,8 +2195,14 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
         // Process task
         match agent.process_task(input).await {
             Ok(result) => {
-                if !result.response.is_empty() {
-                    // Response already printed via callback
+                if let Some(turn) = voice_turn {
+                    turn.respond(result.response.clone());
+                }
+                if let Some(produced) = rustant_core::summarize_artifacts(&result.artifacts) {
+                    println!("\x1b[36m  produced: {}\x1b[0m", produced);
+                }
+                if let Some(grants) = rustant_core::summarize_grants(&result.grants) {
+                    println!("\x1b[33m  grants used: {}\x1b[0m", grants);
                 }
                 println!(
                     "\x1b[90m  [{} iterations, {} tokens, ${:.4}]\x1b[0m",
@@ -1741,6 +2213,9 @@ pub async fn run_interactive(config: AgentConfig, workspace: PathBuf) -> anyhow:
             }
             Err(e) => {
                 println!("\x1b[31mError: {}\x1b[0m", e);
+                if let Some(turn) = voice_turn {
+                    turn.respond(format!("That failed: {}", e));
+                }
                 // Show actionable guidance if available
                 {
                     use rustant_core::error::UserGuidance;
@@ -1783,7 +2258,7 @@ pub async fn run_single_task(
     workspace: PathBuf,
 ) -> anyhow::Result<()> {
     let provider = if config.llm.auth_method == "oauth" {
-        let cred_store = rustant_core::credentials::KeyringCredentialStore::new();
+        let cred_store = rustant_core::credentials::configured_store();
         match rustant_core::create_provider_with_auth(&config.llm, &cred_store).await {
             Ok(p) => p,
             Err(e) => {
@@ -1800,6 +2275,11 @@ pub async fn run_single_task(
             }
         }
     };
+    rustant_tools::reader_capture::set_topic_tagger(Arc::clone(&provider));
+    rustant_tools::lsp::pre_write::install_pre_write_check(
+        &config.tools.pre_write_diagnostics,
+        &workspace,
+    );
     let callback = Arc::new(CliCallback::new(config.ui.verbose));
     // Clone config before moving into Agent (needed for browser setup)
     let config_ref = config.clone();
@@ -1812,6 +2292,9 @@ pub async fn run_single_task(
     // Register browser tools if the browser feature is enabled.
     // Keep _browser_client alive so Chrome stays open for the task duration.
     let _browser_client = try_register_browser_tools(&mut agent, &config_ref, &workspace).await;
+    if let Some(trust_path) = rustant_core::trust::default_trust_path() {
+        agent.load_trust_store(&trust_path);
+    }
 
     match agent.process_task(task).await {
         Ok(result) => {
@@ -1829,17 +2312,23 @@ pub async fn run_single_task(
 }
 
 /// Register tools from the ToolRegistry as agent RegisteredTools.
-fn register_agent_tools_from_registry(
+pub(crate) fn register_agent_tools_from_registry(
     agent: &mut Agent,
     registry: &ToolRegistry,
     workspace: &Path,
 ) {
+    agent.set_workspace(workspace.to_path_buf());
     // Re-create tool executors for the agent's internal tool model.
     // Tools in create_tool_executor() get purpose-built executors.
     // All other tools (macOS native, etc.) use the ToolRegistry as a
     // generic fallback executor so they are actually callable.
-    let registry_arc = Arc::new(registry.clone());
+    let mut registry = registry.clone();
+    registry.set_list_aliases(agent.config().tools.list_aliases);
+    for alias in registry.aliases().iter() {
+        agent.register_tool_alias(alias.clone());
+    }
     let tool_defs = registry.list_definitions();
+    let registry_arc = Arc::new(registry);
     for def in tool_defs {
         let name = def.name.clone();
         let ws = workspace.to_path_buf();
@@ -1865,9 +2354,19 @@ fn register_agent_tools_from_registry(
                     >
             }) as rustant_core::agent::ToolExecutor
         };
+        if let Some(tool) = registry_arc.get(&name) {
+            agent.register_tool_summarizer(
+                name.clone(),
+                Box::new(move |args| tool.summarize_args(args)),
+            );
+        }
+        let canonical = registry_arc
+            .aliases()
+            .get(&name)
+            .map_or(name.as_str(), |alias| alias.canonical.as_str());
         agent.register_tool(RegisteredTool {
             definition: def,
-            risk_level: tool_risk_level(&name),
+            risk_level: tool_risk_level(canonical),
             executor,
         });
     }
@@ -2071,7 +2570,9 @@ fn create_tool_executor(name: &str, workspace: &Path) -> Option<rustant_core::ag
         }
         #[cfg(target_os = "macos")]
         "imessage_read" => {
-            let tool = Arc::new(rustant_tools::imessage::IMessageReadTool);
+            let tool = Arc::new(rustant_tools::imessage::IMessageReadTool::new(
+                workspace.to_path_buf(),
+            ));
             Some(Box::new(move |args| {
                 let t = tool.clone();
                 Box::pin(async move {
@@ -2139,6 +2640,51 @@ fn handle_audit_command(sub: &str, _arg: &str, agent: &Agent) {
                         let decision = if *approved { "yes" } else { "no" };
                         format!("DECISION  {} -> {}", tool, decision)
                     }
+                    rustant_core::safety::AuditEvent::ContractViolation {
+                        tool,
+                        contract,
+                        condition,
+                        ..
+                    } => format!("CONTRACT  {} ({} {})", tool, contract, condition),
+                    rustant_core::safety::AuditEvent::CapabilityBlocked {
+                        tool,
+                        capability,
+                        target,
+                        ..
+                    } => format!("BLOCKED   {} ({} {})", tool, capability, target),
+                    rustant_core::safety::AuditEvent::CapabilityGranted {
+                        tool,
+                        capability,
+                        target,
+                    } => format!("GRANTED   {} ({} {})", tool, capability, target),
+                    rustant_core::safety::AuditEvent::DevicePairing {
+                        action,
+                        device,
+                        detail,
+                    } => format!("DEVICE    {} {} ({})", action, device, detail),
+                    rustant_core::safety::AuditEvent::AgentViolation {
+                        agent_id,
+                        tool,
+                        limit,
+                        ..
+                    } => format!("AGENT     {} {} {}", agent_id, limit, tool),
+                    rustant_core::safety::AuditEvent::ClientDisconnected {
+                        connection_id,
+                        client,
+                        ..
+                    } => format!("CLIENT    disconnected {} ({})", client, connection_id),
+                    rustant_core::safety::AuditEvent::ApprovalExpired {
+                        tool,
+                        policy,
+                        resolution,
+                        ..
+                    } => format!("EXPIRED   {} ({}, {})", tool, resolution, policy),
+                    rustant_core::safety::AuditEvent::FormSubmitted {
+                        title,
+                        client,
+                        values,
+                        ..
+                    } => format!("FORM      {} by {}: {}", title, client, values),
                 };
                 println!("  [{}] {}", ts, desc);
             }
@@ -2156,7 +2702,12 @@ fn handle_audit_command(sub: &str, _arg: &str, agent: &Agent) {
                 println!("No audit entries to export.");
                 return;
             }
-            let format = _arg;
+            let mut words = _arg.split_whitespace();
+            let format = words.next().unwrap_or("");
+            if let Some(siem) = rustant_core::audit::SiemFormat::parse(format) {
+                export_audit_siem(siem, words, log);
+                return;
+            }
             match format {
                 "json" => match serde_json::to_string_pretty(&log) {
                     Ok(json) => println!("{}", json),
@@ -2209,6 +2760,83 @@ fn handle_audit_command(sub: &str, _arg: &str, agent: &Agent) {
                                 tool.as_str(),
                                 format!("approved={}", approved),
                             ),
+                            rustant_core::safety::AuditEvent::ContractViolation {
+                                tool,
+                                contract,
+                                condition,
+                                detail,
+                            } => (
+                                "contract_violation",
+                                tool.as_str(),
+                                format!("{} {}: {}", contract, condition, detail),
+                            ),
+                            rustant_core::safety::AuditEvent::CapabilityBlocked {
+                                tool,
+                                capability,
+                                target,
+                                reason,
+                            } => (
+                                "capability_blocked",
+                                tool.as_str(),
+                                format!("{} {}: {}", capability, target, reason),
+                            ),
+                            rustant_core::safety::AuditEvent::CapabilityGranted {
+                                tool,
+                                capability,
+                                target,
+                            } => (
+                                "capability_granted",
+                                tool.as_str(),
+                                format!("{} {}", capability, target),
+                            ),
+                            rustant_core::safety::AuditEvent::DevicePairing {
+                                action,
+                                device,
+                                detail,
+                            } => (
+                                "device_pairing",
+                                "gateway",
+                                format!("{} {}: {}", action, device, detail),
+                            ),
+                            rustant_core::safety::AuditEvent::AgentViolation {
+                                agent_id,
+                                tool,
+                                limit,
+                                detail,
+                            } => (
+                                "agent_violation",
+                                tool.as_str(),
+                                format!("{} {}: {}", agent_id, limit, detail),
+                            ),
+                            rustant_core::safety::AuditEvent::ClientDisconnected {
+                                connection_id,
+                                client,
+                                detail,
+                            } => (
+                                "client_disconnected",
+                                "gateway",
+                                format!("{} {}: {}", connection_id, client, detail),
+                            ),
+                            rustant_core::safety::AuditEvent::ApprovalExpired {
+                                tool,
+                                policy,
+                                resolution,
+                                escalated,
+                            } => (
+                                "approval_expired",
+                                tool.as_str(),
+                                format!("{} ({}, escalated={})", resolution, policy, escalated),
+                            ),
+                            rustant_core::safety::AuditEvent::FormSubmitted {
+                                form_id,
+                                title,
+                                client,
+                                values,
+                            } => (
+                                "form_submitted",
+                                "gateway",
+                                format!("{} {} by {}: {}", form_id, title, client, values),
+                            ),
                         };
                         println!(
                             "{},{},{},{},\"{}\"",
@@ -2221,7 +2849,7 @@ fn handle_audit_command(sub: &str, _arg: &str, agent: &Agent) {
                     }
                 }
                 _ => println!(
-                    "Unknown format '{}'. Supported: json, jsonl, csv, text",
+                    "Unknown format '{}'. Supported: json, jsonl, csv, text, ocsf, cef",
                     format
                 ),
             }
@@ -2236,13 +2864,21 @@ fn handle_audit_command(sub: &str, _arg: &str, agent: &Agent) {
             let matches: Vec<_> = log
                 .iter()
                 .filter(|entry| {
-                    let entry_tool = match &entry.event {
+                    let entry_tool: &str = match &entry.event {
                         rustant_core::safety::AuditEvent::ActionRequested { tool, .. } => tool,
                         rustant_core::safety::AuditEvent::ActionApproved { tool } => tool,
                         rustant_core::safety::AuditEvent::ActionDenied { tool, .. } => tool,
                         rustant_core::safety::AuditEvent::ActionExecuted { tool, .. } => tool,
                         rustant_core::safety::AuditEvent::ApprovalRequested { tool, .. } => tool,
                         rustant_core::safety::AuditEvent::ApprovalDecision { tool, .. } => tool,
+                        rustant_core::safety::AuditEvent::ContractViolation { tool, .. } => tool,
+                        rustant_core::safety::AuditEvent::CapabilityBlocked { tool, .. } => tool,
+                        rustant_core::safety::AuditEvent::CapabilityGranted { tool, .. } => tool,
+                        rustant_core::safety::AuditEvent::DevicePairing { .. } => "gateway",
+                        rustant_core::safety::AuditEvent::AgentViolation { tool, .. } => tool,
+                        rustant_core::safety::AuditEvent::ClientDisconnected { .. } => "gateway",
+                        rustant_core::safety::AuditEvent::ApprovalExpired { tool, .. } => tool,
+                        rustant_core::safety::AuditEvent::FormSubmitted { .. } => "gateway",
                     };
                     entry_tool == tool_name
                 })
@@ -2284,23 +2920,167 @@ fn handle_audit_command(sub: &str, _arg: &str, agent: &Agent) {
                             let decision = if *approved { "yes" } else { "no" };
                             format!("DECISION  {} -> {}", tool, decision)
                         }
+                        rustant_core::safety::AuditEvent::ContractViolation {
+                            tool,
+                            contract,
+                            condition,
+                            ..
+                        } => format!("CONTRACT  {} ({} {})", tool, contract, condition),
+                        rustant_core::safety::AuditEvent::CapabilityBlocked {
+                            tool,
+                            capability,
+                            target,
+                            ..
+                        } => format!("BLOCKED   {} ({} {})", tool, capability, target),
+                        rustant_core::safety::AuditEvent::CapabilityGranted {
+                            tool,
+                            capability,
+                            target,
+                        } => format!("GRANTED   {} ({} {})", tool, capability, target),
+                        rustant_core::safety::AuditEvent::DevicePairing {
+                            action,
+                            device,
+                            detail,
+                        } => format!("DEVICE    {} {} ({})", action, device, detail),
+                        rustant_core::safety::AuditEvent::AgentViolation {
+                            agent_id,
+                            tool,
+                            limit,
+                            ..
+                        } => format!("AGENT     {} {} {}", agent_id, limit, tool),
+                        rustant_core::safety::AuditEvent::ClientDisconnected {
+                            connection_id,
+                            client,
+                            ..
+                        } => format!("CLIENT    disconnected {} ({})", client, connection_id),
+                        rustant_core::safety::AuditEvent::ApprovalExpired {
+                            tool,
+                            policy,
+                            resolution,
+                            ..
+                        } => format!("EXPIRED   {} ({}, {})", tool, resolution, policy),
+                        rustant_core::safety::AuditEvent::FormSubmitted {
+                            title,
+                            client,
+                            values,
+                            ..
+                        } => format!("FORM      {} by {}: {}", title, client, values),
                     };
                     println!("  [{}] {}", ts, desc);
                 }
             }
         }
         _ => {
-            println!("Usage: /audit [show [n] | verify | export [fmt] | query <tool>]");
+            println!("Usage: /audit [show [n] | verify | export [fmt] [opts] | query <tool>]");
         }
     }
 }
 
+/// Export the session audit log for SIEM ingestion:
+/// `/audit export ocsf|cef [since=<time>] [until=<time>] [type=<tag,..>] [out=<path>]`.
+///
+/// Events stream to stdout, or to `out` with the export metadata (including
+/// Merkle chain status) written next to it as `<out>.meta.json`.
+fn export_audit_siem<'a>(
+    format: rustant_core::audit::SiemFormat,
+    options: impl Iterator<Item = &'a str>,
+    log: &std::collections::VecDeque<rustant_core::safety::AuditEntry>,
+) {
+    use rustant_core::audit::{
+        AuditExporter, AuditQuery, AuditStore, TraceEventKind, parse_time_bound,
+    };
+
+    let now = chrono::Utc::now();
+    let mut query = AuditQuery::new();
+    let mut out_path: Option<PathBuf> = None;
+    for option in options {
+        let Some((key, value)) = option.split_once('=') else {
+            println!("Expected key=value, got '{}'.", option);
+            return;
+        };
+        match key {
+            "since" | "until" => {
+                let Some(bound) = parse_time_bound(value, now) else {
+                    println!(
+                        "Invalid time '{}'. Use RFC 3339, YYYY-MM-DD, or an age like 12h or 7d.",
+                        value
+                    );
+                    return;
+                };
+                query = if key == "since" {
+                    query.since(bound)
+                } else {
+                    query.until(bound)
+                };
+            }
+            "type" => {
+                for tag in value.split(',') {
+                    if !TraceEventKind::TYPE_TAGS.contains(&tag) {
+                        println!(
+                            "Unknown event type '{}'. Known types: {}",
+                            tag,
+                            TraceEventKind::TYPE_TAGS.join(", ")
+                        );
+                        return;
+                    }
+                    query = query.for_event_type(tag);
+                }
+            }
+            "out" => out_path = Some(PathBuf::from(value)),
+            _ => {
+                println!(
+                    "Unknown export option '{}'. Options: since, until, type, out",
+                    key
+                );
+                return;
+            }
+        }
+    }
+
+    let store = AuditStore::from_audit_log(log);
+    let Some(path) = out_path else {
+        match AuditExporter::write_siem(&store, &query, format, io::stdout().lock()) {
+            Ok(meta) => eprintln!("{} events exported, chain: {}", meta.events, meta.chain),
+            Err(e) => println!("Export error: {}", e),
+        }
+        return;
+    };
+    let file = match std::fs::File::create(&path) {
+        Ok(file) => file,
+        Err(e) => {
+            println!("Cannot create {}: {}", path.display(), e);
+            return;
+        }
+    };
+    let meta = match AuditExporter::write_siem(&store, &query, format, file) {
+        Ok(meta) => meta,
+        Err(e) => {
+            println!("Export error: {}", e);
+            return;
+        }
+    };
+    let meta_path = PathBuf::from(format!("{}.meta.json", path.display()));
+    let written = serde_json::to_string_pretty(&meta)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&meta_path, json).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        println!("Cannot write {}: {}", meta_path.display(), e);
+    }
+    println!(
+        "Exported {} events from {} sessions to {} (chain: {}).",
+        meta.events,
+        meta.traces,
+        path.display(),
+        meta.chain
+    );
+}
+
 /// Handle `/session` subcommands.
 fn handle_session_command(sub: &str, name: &str, agent: &mut Agent, workspace: &Path) {
     match sub {
         "save" => {
             let session_name = if name.is_empty() { None } else { Some(name) };
-            let mut mgr = match rustant_core::SessionManager::new(workspace) {
+            let mut mgr = match crate::commands::open_sessions(workspace) {
                 Ok(m) => m,
                 Err(e) => {
                     println!("Failed to initialize session manager: {}", e);
@@ -2308,6 +3088,19 @@ fn handle_session_command(sub: &str, name: &str, agent: &mut Agent, workspace: &
                 }
             };
             let entry = mgr.start_session(session_name);
+            let persona = agent.active_persona().map(|p| p.name.as_str());
+            if let Err(e) = mgr.set_active_persona(persona) {
+                tracing::warn!("Failed to record persona: {}", e);
+            }
+            if let Err(e) = mgr.set_artifacts(agent.artifacts()) {
+                tracing::warn!("Failed to record artifacts: {}", e);
+            }
+            if let Err(e) = mgr.set_task_runs(agent.task_runs()) {
+                tracing::warn!("Failed to record task runs: {}", e);
+            }
+            if let Err(e) = mgr.save_recording(agent.recording()) {
+                tracing::warn!("Failed to save session recording: {}", e);
+            }
             let total_tokens = agent.brain().total_usage().total();
             match mgr.save_checkpoint(agent.memory(), total_tokens) {
                 Ok(()) => println!("Session '{}' saved.", entry.name),
@@ -2319,7 +3112,7 @@ fn handle_session_command(sub: &str, name: &str, agent: &mut Agent, workspace: &
                 println!("Usage: /session load <name>");
                 return;
             }
-            let mut mgr = match rustant_core::SessionManager::new(workspace) {
+            let mut mgr = match crate::commands::open_sessions(workspace) {
                 Ok(m) => m,
                 Err(e) => {
                     println!("Failed to initialize session manager: {}", e);
@@ -2329,6 +3122,9 @@ fn handle_session_command(sub: &str, name: &str, agent: &mut Agent, workspace: &
             match mgr.resume_session(name) {
                 Ok((mem, continuation)) => {
                     *agent.memory_mut() = mem;
+                    agent.set_artifacts(mgr.active_artifacts());
+                    agent.set_task_runs(mgr.active_task_runs());
+                    agent.set_recording(mgr.active_recording());
                     println!("Session '{}' loaded.", name);
                     if !continuation.is_empty() {
                         println!("{}", continuation);
@@ -2380,12 +3176,88 @@ fn handle_memory_command(agent: &Agent) {
     println!("    Preferences: {}", mem.long_term.preferences.len());
 }
 
-/// Handle `/pin <n>` command to pin a message by position.
-fn handle_pin_command(arg: &str, agent: &mut Agent) {
+/// Print the facts learned in this session.
+fn print_learned(agent: &Agent) {
+    let learned = agent.learned();
+    if learned.is_empty() {
+        println!("Nothing learned this session.");
+        return;
+    }
+    if !learned.stored.is_empty() {
+        println!("Learned this session:");
+        for fact in &learned.stored {
+            println!("  + [{}] {}", fact.category, fact.content);
+        }
+    }
+    if !learned.queued.is_empty() {
+        println!("Waiting for review (/learned review):");
+        for fact in &learned.queued {
+            println!(
+                "  ? [{}] {} ({:.2})",
+                fact.category, fact.content, fact.confidence
+            );
+        }
+    }
+}
+
+/// Handle `/learned [review|accept <n>|reject <n>]`: show what this session
+/// learned, list the review queue, or accept or reject a queued fact.
+fn handle_learned_command(action: &str, arg: &str, agent: &mut Agent) {
+    match action {
+        "" => print_learned(agent),
+        "review" => {
+            let review = &agent.learned_facts().review;
+            if review.is_empty() {
+                println!("No learned facts are waiting for review.");
+                return;
+            }
+            println!("Waiting for review ({}):", review.len());
+            for (i, fact) in review.iter().enumerate() {
+                println!(
+                    "  {}. [{}] {} ({:.2}, {})",
+                    i + 1,
+                    fact.category,
+                    fact.content,
+                    fact.confidence,
+                    fact.provenance.learned_at.format("%Y-%m-%d")
+                );
+            }
+            println!("Accept with /learned accept <n>, or reject with /learned reject <n>.");
+        }
+        "accept" | "reject" => {
+            let review = &agent.learned_facts().review;
+            let Some(id) = arg
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| review.get(i))
+                .map(|f| f.id)
+            else {
+                println!("Usage: /learned {} <n> (see /learned review)", action);
+                return;
+            };
+            match agent.review_learned_fact(id, action == "accept") {
+                Ok(Some(fact)) if action == "accept" => println!("Stored: {}", fact.content),
+                Ok(Some(fact)) => println!("Rejected: {}", fact.content),
+                Ok(None) => println!("That fact is no longer waiting for review."),
+                Err(e) => println!("\x1b[31mFailed to save learned facts: {}\x1b[0m", e),
+            }
+        }
+        _ => println!("Usage: /learned [review|accept <n>|reject <n>]"),
+    }
+}
+
+/// Handle `/pin <n>` to pin a message by position, or `/pin <path|fact>` to
+/// pin a file or fact into every prompt.
+fn handle_pin_command(arg: &str, agent: &mut Agent, workspace: &Path) {
     if arg.is_empty() {
         // List pinned messages
         let mem = agent.memory();
         let count = mem.short_term.pinned_count();
+        if !mem.pins.is_empty() {
+            println!("{} pinned files and facts (see /pins).", mem.pins.len());
+        }
         if count == 0 {
             println!("No pinned messages. Use /pin <n> to pin a message by position.");
         } else {
@@ -2424,15 +3296,34 @@ fn handle_pin_command(arg: &str, agent: &mut Agent) {
                     mem.short_term.len().saturating_sub(1)
                 );
             }
-        }
-        Err(_) => {
-            println!("Usage: /pin <message_number>");
-        }
+        }
+        Err(_) => match PinTarget::parse(arg, workspace) {
+            Ok(target) => {
+                if !agent.memory_mut().pins.pin(target, false) {
+                    println!("{} is already pinned.", arg);
+                    return;
+                }
+                let pinned = agent.pinned_context();
+                if let Some(pin) = pinned.pins.last() {
+                    println!(
+                        "Pinned {} (~{} tokens in every prompt){}.",
+                        pin.label,
+                        pin.tokens,
+                        pin.status_note()
+                    );
+                }
+            }
+            Err(e) => println!("{}. Usage: /pin <message_number|path[:start-end]|fact>", e),
+        },
     }
 }
 
-/// Handle `/unpin <n>` command.
+/// Handle `/unpin <n>` or `/unpin <path|fact>`.
 fn handle_unpin_command(arg: &str, agent: &mut Agent) {
+    if arg.is_empty() {
+        println!("Usage: /unpin <message_number|path|fact>");
+        return;
+    }
     match arg.parse::<usize>() {
         Ok(n) => {
             let mem = agent.memory_mut();
@@ -2442,9 +3333,207 @@ fn handle_unpin_command(arg: &str, agent: &mut Agent) {
                 println!("Message #{} was not pinned.", n);
             }
         }
-        Err(_) => {
-            println!("Usage: /unpin <message_number>");
+        Err(_) => match agent.memory_mut().pins.unpin(arg) {
+            Some(pin) => println!("Unpinned {}.", pin.label()),
+            None => println!("Nothing pinned matches '{}'. See /pins.", arg),
+        },
+    }
+}
+
+/// Handle `/pins` to list pinned files and facts with their token cost.
+fn handle_pins_command(agent: &Agent) {
+    let pinned = agent.pinned_context();
+    if pinned.pins.is_empty() {
+        println!("Nothing pinned. Use /pin <path[:start-end]|fact> to keep it in every prompt.");
+        return;
+    }
+    println!("Pinned context ({}):", pinned.pins.len());
+    for pin in &pinned.pins {
+        let by = if pin.by_agent { " [agent]" } else { "" };
+        println!(
+            "  {} - ~{} tokens{}{}",
+            pin.label,
+            pin.tokens,
+            pin.status_note(),
+            by
+        );
+    }
+    println!("  Total: ~{} tokens per prompt", pinned.total_tokens());
+}
+
+/// Handle `/attach` to attach an image file or the clipboard image to the
+/// next message, list attached images, or drop them.
+async fn handle_attach_command(arg: &str, agent: &mut Agent) {
+    match arg {
+        "" => {
+            let staged = agent.staged_images();
+            if staged.is_empty() {
+                println!("No images attached. Use /attach <path> or /attach clipboard.");
+                return;
+            }
+            println!("Attached to your next message ({}):", staged.len());
+            for handle in staged {
+                println!("  {}", handle.reference());
+            }
+        }
+        "clear" => {
+            let count = agent.staged_images().len();
+            agent.clear_staged_images();
+            println!("Dropped {} attached image(s).", count);
+        }
+        "clipboard" => {
+            let result = agent.attach_clipboard_image().await;
+            report_attached_image(result, agent);
+        }
+        path => {
+            let path = path.trim_matches(|c| c == '\'' || c == '"');
+            let path = match path.strip_prefix("~/") {
+                Some(rest) => std::env::var("HOME")
+                    .map(|home| PathBuf::from(home).join(rest))
+                    .unwrap_or_else(|_| PathBuf::from(path)),
+                None => PathBuf::from(path),
+            };
+            report_attached_image(agent.attach_image(&path), agent);
+        }
+    }
+}
+
+/// Handle `/clip` to attach an entry of the clipboard history to the next
+/// message: pick one of the recent entries, name it by id, search, or purge
+/// the history.
+fn handle_clip_command(arg: &str, agent: &mut Agent, workspace: &Path) {
+    use rustant_core::clipboard::ClipboardHistory;
+
+    if !ClipboardHistory::exists(workspace) {
+        println!(
+            "No clipboard history yet. Set [clipboard] history = true and run `rustant ui` to record it."
+        );
+        return;
+    }
+    let mut history = match ClipboardHistory::open(workspace, &agent.config().clipboard) {
+        Ok(history) => history,
+        Err(e) => {
+            println!("\x1b[31mCould not open clipboard history: {}\x1b[0m", e);
+            return;
+        }
+    };
+    let (command, rest) = arg.split_once(' ').unwrap_or((arg, ""));
+    let candidates = match command {
+        "" => history.recent(10),
+        "search" if !rest.trim().is_empty() => history.search(rest.trim(), 10),
+        "purge" => {
+            match history.purge() {
+                Ok(count) => println!(
+                    "Deleted {} clipboard entr{}.",
+                    count,
+                    if count == 1 { "y" } else { "ies" }
+                ),
+                Err(e) => println!("\x1b[31mCould not purge clipboard history: {}\x1b[0m", e),
+            }
+            return;
+        }
+        id => {
+            match id.trim_start_matches('#').parse::<u64>() {
+                Ok(id) => attach_clip(&history, id, agent),
+                Err(_) => println!("Usage: /clip [id|search <text>|purge]"),
+            }
+            return;
+        }
+    };
+    let Some(newest) = candidates.first().map(|e| e.id) else {
+        println!("No matching clipboard entries.");
+        return;
+    };
+    for entry in &candidates {
+        println!(
+            "  \x1b[1m#{:<4}\x1b[0m {:<6} \x1b[90m{}\x1b[0m  {}",
+            entry.id,
+            entry.kind,
+            entry
+                .copied_at
+                .with_timezone(&chrono::Local)
+                .format("%H:%M"),
+            entry.preview(60)
+        );
+    }
+    print!("Attach # (Enter for #{}, q to cancel) > ", newest);
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return;
+    }
+    let answer = answer.trim().trim_start_matches('#');
+    let id = match answer {
+        "" => newest,
+        "q" | "n" => return,
+        other => match other.parse::<u64>() {
+            Ok(id) => id,
+            Err(_) => {
+                println!("Not an entry number: {}", other);
+                return;
+            }
+        },
+    };
+    attach_clip(&history, id, agent);
+}
+
+/// Attach clipboard entry `id` to the next message: images as attachment
+/// handles, everything else as a labeled text block.
+fn attach_clip(history: &rustant_core::clipboard::ClipboardHistory, id: u64, agent: &mut Agent) {
+    use rustant_core::clipboard::ClipKind;
+
+    let entry = match history.get(id) {
+        Ok(entry) => entry,
+        Err(e) => {
+            println!("\x1b[31m{}\x1b[0m", e);
+            return;
+        }
+    };
+    if entry.kind == ClipKind::Image {
+        match history.image_bytes(entry) {
+            Ok(bytes) => {
+                let result = agent.attach_image_bytes(bytes, format!("Clipboard image #{}", id));
+                report_attached_image(result, agent);
+            }
+            Err(e) => println!("\x1b[31mCould not read clipboard image: {}\x1b[0m", e),
+        }
+        return;
+    }
+    agent.attach_text(entry.context_block());
+    println!(
+        "\x1b[36m  Attached clipboard entry #{} ({}) to your next message\x1b[0m",
+        id, entry.kind
+    );
+    if entry.redacted {
+        println!("\x1b[90m  Credentials in it were redacted when it was recorded\x1b[0m");
+    }
+}
+
+/// Print the outcome of attaching an image, with its estimated cost for the
+/// current model, or how it will be described if the model has no vision.
+fn report_attached_image(
+    result: Result<rustant_core::types::AttachmentHandle, rustant_core::AttachmentError>,
+    agent: &Agent,
+) {
+    let handle = match result {
+        Ok(handle) => handle,
+        Err(e) => {
+            println!("\x1b[31mCould not attach image: {}\x1b[0m", e);
+            return;
         }
+    };
+    println!("\x1b[36m  Attached {}\x1b[0m", handle.reference());
+    if agent.brain().provider().supports_vision() {
+        let (tokens, cost) = agent.brain().image_cost(&handle);
+        println!(
+            "\x1b[90m  ~{} input tokens (${:.4}) per request that includes it\x1b[0m",
+            tokens, cost
+        );
+    } else {
+        println!(
+            "\x1b[33m  {} cannot see images; a text description will be sent instead\x1b[0m",
+            agent.brain().model_name()
+        );
     }
 }
 
@@ -2452,10 +3541,14 @@ fn handle_unpin_command(arg: &str, agent: &mut Agent) {
 fn handle_context_command(agent: &Agent) {
     let context_window = agent.brain().context_window();
     let mem = agent.memory();
-    let ctx = mem.context_breakdown(context_window);
+    let counter = agent.brain().token_counter();
+    let mut ctx = mem.context_breakdown_with(context_window, counter);
+    let pinned = agent.pinned_context();
+    ctx.add_pinned(pinned.total_tokens());
 
     println!("Context Window Breakdown:");
     println!("  Window size: {} tokens", ctx.context_window);
+    println!("  Tokenizer:   {}", counter.tokenizer());
     println!("  ──────────────────────────");
     if ctx.has_summary {
         println!("  Summary:    ~{} tokens", ctx.summary_tokens);
@@ -2470,6 +3563,20 @@ fn handle_context_command(agent: &Agent) {
             ctx.pinned_count
         );
     }
+    if !pinned.pins.is_empty() {
+        println!(
+            "  Pinned context: ~{} tokens (exempt from compression)",
+            ctx.pinned_tokens
+        );
+        for pin in &pinned.pins {
+            println!(
+                "    {} - ~{} tokens{}",
+                pin.label,
+                pin.tokens,
+                pin.status_note()
+            );
+        }
+    }
     println!("  ──────────────────────────");
     println!(
         "  Total used: ~{} tokens ({:.0}%)",
@@ -2534,7 +3641,7 @@ fn handle_workflows_command() {
 
 /// Handle `/resume` REPL command.
 fn handle_resume_command(query: &str, agent: &mut Agent, workspace: &Path) {
-    let mut mgr = match rustant_core::SessionManager::new(workspace) {
+    let mut mgr = match crate::commands::open_sessions(workspace) {
         Ok(m) => m,
         Err(e) => {
             println!("Failed to initialize session manager: {}", e);
@@ -2553,6 +3660,9 @@ fn handle_resume_command(query: &str, agent: &mut Agent, workspace: &Path) {
             let goal = memory.working.current_goal.clone().unwrap_or_default();
             let msg_count = memory.short_term.len();
             *agent.memory_mut() = memory;
+            agent.set_artifacts(mgr.active_artifacts());
+            agent.set_task_runs(mgr.active_task_runs());
+            agent.set_recording(mgr.active_recording());
             agent
                 .memory_mut()
                 .add_message(rustant_core::types::Message::system(continuation));
@@ -2569,11 +3679,221 @@ fn handle_resume_command(query: &str, agent: &mut Agent, workspace: &Path) {
     }
 }
 
+/// Handle `/fork [<turn> [session]]`: branch a session after its first
+/// `turn` user turns and continue on the branch.
+///
+/// Without a session the live conversation is saved first and forked. With
+/// no turn, lists the turns of the live conversation.
+fn handle_fork_command(turn: &str, session: &str, agent: &mut Agent, workspace: &Path) {
+    if turn.is_empty() {
+        let turns: Vec<_> = agent
+            .memory()
+            .short_term
+            .messages()
+            .iter()
+            .filter(|m| m.role == rustant_core::types::Role::User)
+            .filter_map(|m| m.content.as_text())
+            .collect();
+        if turns.is_empty() {
+            println!("No turns to fork from yet.");
+            return;
+        }
+        println!("Turns:");
+        for (i, text) in turns.iter().enumerate() {
+            let text = text.lines().next().unwrap_or_default();
+            if text.chars().count() > 60 {
+                println!("  {:>3}. {}...", i + 1, truncate_str(text, 60));
+            } else {
+                println!("  {:>3}. {}", i + 1, text);
+            }
+        }
+        println!("Usage: /fork <turn> [session]");
+        return;
+    }
+    let Ok(turn) = turn.parse::<usize>() else {
+        println!("Usage: /fork <turn> [session]");
+        return;
+    };
+    let mut mgr = match crate::commands::open_sessions(workspace) {
+        Ok(m) => m,
+        Err(e) => {
+            println!("Failed to initialize session manager: {}", e);
+            return;
+        }
+    };
+
+    let parent = if session.is_empty() {
+        let entry = mgr.start_session(None);
+        let persona = agent.active_persona().map(|p| p.name.as_str());
+        if let Err(e) = mgr.set_active_persona(persona) {
+            tracing::warn!("Failed to record persona: {}", e);
+        }
+        if let Err(e) = mgr.set_artifacts(agent.artifacts()) {
+            tracing::warn!("Failed to record artifacts: {}", e);
+        }
+        if let Err(e) = mgr.set_task_runs(agent.task_runs()) {
+            tracing::warn!("Failed to record task runs: {}", e);
+        }
+        if let Err(e) = mgr.save_recording(agent.recording()) {
+            tracing::warn!("Failed to save session recording: {}", e);
+        }
+        let total_tokens = agent.brain().total_usage().total();
+        if let Err(e) = mgr.save_checkpoint(agent.memory(), total_tokens) {
+            println!("Failed to save session: {}", e);
+            return;
+        }
+        println!("Current conversation saved as '{}'.", entry.name);
+        entry.id.to_string()
+    } else {
+        session.to_string()
+    };
+
+    match mgr.fork_session(&parent, turn, None) {
+        Ok(branch) => {
+            println!(
+                "\x1b[32mForked at turn {} into '{}'.\x1b[0m",
+                turn, branch.name
+            );
+            handle_resume_command(&branch.id.to_string(), agent, workspace);
+            println!("Compare outcomes later with /compare {}", branch.name);
+        }
+        Err(e) => println!("Fork failed: {}", e),
+    }
+}
+
+/// Handle `/compare <branch> [other]`: show how the artifacts of two
+/// branches differ since they diverged (`other` defaults to the parent).
+fn handle_compare_command(branch: &str, other: &str, workspace: &Path) {
+    use rustant_core::BranchDiff;
+
+    if branch.is_empty() {
+        println!("Usage: /compare <branch> [other-branch]");
+        return;
+    }
+    let mgr = match crate::commands::open_sessions(workspace) {
+        Ok(m) => m,
+        Err(e) => {
+            println!("Failed to initialize session manager: {}", e);
+            return;
+        }
+    };
+    let other = (!other.is_empty()).then_some(other);
+    let comparison = match mgr.compare_branches(branch, other) {
+        Ok(c) => c,
+        Err(e) => {
+            println!("Compare failed: {}", e);
+            return;
+        }
+    };
+
+    if comparison.base == comparison.right {
+        println!(
+            "Comparing branch '{}' with its parent '{}':",
+            comparison.left, comparison.right
+        );
+    } else if comparison.base == comparison.left {
+        println!(
+            "Comparing '{}' with its branch '{}':",
+            comparison.left, comparison.right
+        );
+    } else {
+        println!(
+            "Comparing '{}' with '{}' (both forked from '{}'):",
+            comparison.left, comparison.right, comparison.base
+        );
+    }
+    if comparison.changes.is_empty() {
+        println!("  Neither branch produced artifacts since they diverged.");
+    }
+    for change in &comparison.changes {
+        let status = match change.status {
+            BranchDiff::OnlyLeft => format!("\x1b[32monly in {}\x1b[0m", comparison.left),
+            BranchDiff::OnlyRight => format!("\x1b[36monly in {}\x1b[0m", comparison.right),
+            BranchDiff::Differs => "\x1b[33mdiffers\x1b[0m".to_string(),
+            BranchDiff::Same => "same".to_string(),
+        };
+        println!(
+            "  {:<12} {} [{}]",
+            change.kind.to_string(),
+            change.item,
+            status
+        );
+    }
+    println!("Keep one with /resume <name> and discard the other with /sessions delete <name>.");
+}
+
+/// Handle `/workspace [name]`: show the named workspaces, or switch to one.
+///
+/// The current session and scheduler state are saved to the old workspace,
+/// then memory, tools and scheduler are rebuilt for the new one and its
+/// latest session is resumed. Returns the new workspace on a switch.
+fn handle_workspace_command(name: &str, agent: &mut Agent, workspace: &Path) -> Option<PathBuf> {
+    let registry = crate::commands::load_workspace_registry();
+    if name.is_empty() {
+        println!(
+            "Workspace: {}",
+            rustant_core::workspaces::workspace_label(&registry, workspace)
+        );
+        if registry.is_empty() {
+            println!(
+                "No named workspaces. Add one with: rustant workspace add <path> --name <name>"
+            );
+        }
+        for entry in registry.list() {
+            let marker = if entry.path == workspace { "*" } else { " " };
+            println!("{} {:<16} {}", marker, entry.name, entry.path.display());
+        }
+        return None;
+    }
+    let target = match registry.resolve(name) {
+        Ok(path) => path,
+        Err(e) => {
+            println!("\x1b[31m{}\x1b[0m", e);
+            return None;
+        }
+    };
+    if target == workspace {
+        println!("Already in workspace '{}'.", name);
+        return None;
+    }
+
+    if agent.memory().short_term.total_messages_seen() > 0 {
+        handle_session_command("save", "", agent, workspace);
+    }
+    auto_save_scheduler(agent, workspace);
+
+    agent.switch_workspace(target.clone());
+    let mut registry = ToolRegistry::new();
+    register_builtin_tools(&mut registry, target.clone());
+    register_agent_tools_from_registry(agent, &registry, &target);
+    agent.load_scheduler_state(&target.join(".rustant").join("scheduler"));
+    println!(
+        "\x1b[32mSwitched to workspace '{}'\x1b[0m ({})",
+        name,
+        target.display()
+    );
+    if let Ok(mut mgr) = crate::commands::open_sessions(&target)
+        && let Ok((memory, _continuation)) = mgr.resume_latest()
+        && !memory.short_term.is_empty()
+    {
+        let msg_count = memory.short_term.len();
+        *agent.memory_mut() = memory;
+        agent.set_artifacts(mgr.active_artifacts());
+        agent.set_task_runs(mgr.active_task_runs());
+        agent.set_recording(mgr.active_recording());
+        println!(
+            "\x1b[90m  Recovered its latest session ({} messages).\x1b[0m",
+            msg_count
+        );
+    }
+    Some(target)
+}
+
 /// Handle `/sessions` REPL command with optional subcommands.
 fn handle_sessions_command(sub: &str, arg: &str, workspace: &Path) {
     match sub {
         "search" if !arg.is_empty() => {
-            let mgr = match rustant_core::SessionManager::new(workspace) {
+            let mgr = match crate::commands::open_sessions(workspace) {
                 Ok(m) => m,
                 Err(e) => {
                     println!("Failed to initialize session manager: {}", e);
@@ -2581,7 +3901,12 @@ fn handle_sessions_command(sub: &str, arg: &str, workspace: &Path) {
                 }
             };
             let results = mgr.search(arg);
-            if results.is_empty() {
+            let in_transcripts: Vec<_> = mgr
+                .search_transcripts(arg)
+                .into_iter()
+                .filter(|e| !results.iter().any(|r| r.id == e.id))
+                .collect();
+            if results.is_empty() && in_transcripts.is_empty() {
                 println!("No sessions matching '{}'.", arg);
                 return;
             }
@@ -2589,6 +3914,12 @@ fn handle_sessions_command(sub: &str, arg: &str, workspace: &Path) {
             for entry in &results {
                 print_session_entry(entry);
             }
+            if !in_transcripts.is_empty() {
+                println!("Mentioned in transcripts:");
+                for entry in &in_transcripts {
+                    print_session_entry(entry);
+                }
+            }
         }
         "tag" if !arg.is_empty() => {
             let parts: Vec<&str> = arg.splitn(2, ' ').collect();
@@ -2596,7 +3927,7 @@ fn handle_sessions_command(sub: &str, arg: &str, workspace: &Path) {
                 println!("Usage: /sessions tag <session-name> <tag>");
                 return;
             }
-            let mut mgr = match rustant_core::SessionManager::new(workspace) {
+            let mut mgr = match crate::commands::open_sessions(workspace) {
                 Ok(m) => m,
                 Err(e) => {
                     println!("Failed to initialize session manager: {}", e);
@@ -2608,8 +3939,21 @@ fn handle_sessions_command(sub: &str, arg: &str, workspace: &Path) {
                 Err(e) => println!("Failed to tag session: {}", e),
             }
         }
+        "delete" if !arg.is_empty() => {
+            let mut mgr = match crate::commands::open_sessions(workspace) {
+                Ok(m) => m,
+                Err(e) => {
+                    println!("Failed to initialize session manager: {}", e);
+                    return;
+                }
+            };
+            match mgr.delete_session(arg) {
+                Ok(name) => println!("Deleted session '{}'.", name),
+                Err(e) => println!("Failed to delete session: {}", e),
+            }
+        }
         "filter" if !arg.is_empty() => {
-            let mgr = match rustant_core::SessionManager::new(workspace) {
+            let mgr = match crate::commands::open_sessions(workspace) {
                 Ok(m) => m,
                 Err(e) => {
                     println!("Failed to initialize session manager: {}", e);
@@ -2628,24 +3972,27 @@ fn handle_sessions_command(sub: &str, arg: &str, workspace: &Path) {
         }
         _ => {
             // Default: list sessions
-            let mgr = match rustant_core::SessionManager::new(workspace) {
+            let mgr = match crate::commands::open_sessions(workspace) {
                 Ok(m) => m,
                 Err(e) => {
                     println!("Failed to initialize session manager: {}", e);
                     return;
                 }
             };
-            let sessions = mgr.list_sessions(10);
+            let sessions = mgr.index().list_tree(10);
             if sessions.is_empty() {
                 println!("No saved sessions found.");
                 return;
             }
             println!("Saved sessions:");
-            for entry in &sessions {
+            for (depth, entry) in &sessions {
+                if *depth > 0 {
+                    print!("{}  └─", "    ".repeat(depth - 1));
+                }
                 print_session_entry(entry);
             }
             println!(
-                "\nCommands: /sessions search <query> | /sessions tag <name> <tag> | /sessions filter <tag>"
+                "\nCommands: /sessions search <query> | /sessions tag <name> <tag> | /sessions filter <tag> | /sessions delete <name>"
             );
             println!("Resume with: /resume <name>");
         }
@@ -3038,7 +4385,19 @@ fn handle_permissions_command(arg: &str, agent: &mut Agent) {
 }
 
 /// Handle `/trust` command to show safety trust dashboard.
-fn handle_trust_command(agent: &Agent) {
+fn handle_trust_command(arg1: &str, arg2: &str, agent: &mut Agent) {
+    if arg1 == "reset" {
+        let workspace = agent.safety().adaptive_trust().workspace().to_string();
+        match rustant_core::trust::TrustScope::parse(arg2, &workspace) {
+            Some(scope) => {
+                let removed = agent.safety_mut().reset_trust(&scope);
+                println!("Reset {} trust score(s).", removed);
+            }
+            None => println!("Usage: /trust reset <tool|workspace [path]|all>"),
+        }
+        return;
+    }
+
     let safety = agent.safety();
     let mode = safety.approval_mode();
     let mode_desc = match format!("{:?}", mode).to_lowercase().as_str() {
@@ -3135,6 +4494,20 @@ fn handle_trust_command(agent: &Agent) {
         }
     }
 
+    let trust = safety.adaptive_trust();
+    if trust.enabled {
+        println!();
+        let report = trust.ledger.report(
+            &trust.policy,
+            &rustant_core::trust::TrustScope::All,
+            chrono::Utc::now(),
+        );
+        for line in report.lines() {
+            println!("  {}", line);
+        }
+        println!("  Reset with: /trust reset <tool|workspace [path]|all>");
+    }
+
     println!();
     println!("  Change mode with: /permissions <safe|cautious|paranoid|yolo>");
 }
@@ -3940,9 +5313,15 @@ fn handle_schedule_command(
                             .next_run
                             .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
                             .unwrap_or_else(|| "N/A".to_string());
+                        let tag = job
+                            .config
+                            .workspace
+                            .as_deref()
+                            .map(|w| format!(" [{}]", crate::commands::workspace_label(w)))
+                            .unwrap_or_default();
                         println!(
-                            "  {} [{}] -- next: {} -- runs: {} -- {}",
-                            job.config.name, status, next, job.run_count, job.config.task
+                            "  {} [{}] -- next: {} -- runs: {} -- {}{}",
+                            job.config.name, status, next, job.run_count, job.config.task, tag
                         );
                     }
                 }
@@ -3968,7 +5347,8 @@ fn handle_schedule_command(
             let cron_expr = words[..7].join(" ");
             let task = words[7..].join(" ");
 
-            let config = rustant_core::scheduler::CronJobConfig::new(name, &cron_expr, &task);
+            let mut config = rustant_core::scheduler::CronJobConfig::new(name, &cron_expr, &task);
+            config.workspace = Some(workspace.to_path_buf());
             if let Some(scheduler) = agent.cron_scheduler_mut() {
                 match scheduler.add_job(config) {
                     Ok(()) => {
@@ -4051,9 +5431,14 @@ fn handle_schedule_command(
                             format!("{}s", dur.num_seconds())
                         })
                         .unwrap_or_else(|| "running".to_string());
+                    let tag = job
+                        .workspace
+                        .as_deref()
+                        .map(|w| format!(" [{}]", crate::commands::workspace_label(w)))
+                        .unwrap_or_default();
                     println!(
-                        "  {} [{}] -- {} -- {}",
-                        job.name, job.status, duration, job.id
+                        "  {} [{}] -- {} -- {}{}",
+                        job.name, job.status, duration, job.id, tag
                     );
                 }
             }
@@ -4083,6 +5468,165 @@ fn handle_schedule_command(
     }
 }
 
+/// Activate (or clear, with `None`) a persona on the agent.
+///
+/// Swaps the LLM provider when the persona prefers a different model than the
+/// one currently in use. Returns a human-readable status line.
+pub(crate) fn switch_persona(
+    agent: &mut Agent,
+    config: &AgentConfig,
+    name: Option<&str>,
+) -> Result<String, String> {
+    let persona = match name {
+        Some(name) => Some(
+            rustant_core::PersonaRegistry::from_config(&config.personas)
+                .get(name)
+                .map_err(|e| e.to_string())?
+                .clone(),
+        ),
+        None => None,
+    };
+
+    let desired_model = persona
+        .as_ref()
+        .and_then(|p| p.model.clone())
+        .unwrap_or_else(|| config.llm.model.clone());
+    let mut model_note = String::new();
+    if desired_model != agent.brain().model_name() {
+        let mut llm = config.llm.clone();
+        llm.model = desired_model.clone();
+        match rustant_core::create_provider(&llm) {
+            Ok(provider) => {
+                agent.set_provider(provider);
+                model_note = format!(" (model: {})", desired_model);
+            }
+            Err(e) => {
+                tracing::warn!("Could not switch to model '{}': {}", desired_model, e);
+            }
+        }
+    }
+
+    let message = match &persona {
+        Some(p) => format!(
+            "Persona '{}' active{}. Approval: {}",
+            p.name,
+            model_note,
+            p.approval_mode.unwrap_or(agent.safety().approval_mode())
+        ),
+        None => format!("Persona cleared{}.", model_note),
+    };
+    agent.set_persona(persona);
+    Ok(message)
+}
+
+/// Handle the /persona command -- list, switch, or clear personas.
+fn handle_persona_command(arg: &str, agent: &mut Agent, config: &AgentConfig) {
+    match arg {
+        "" => match agent.active_persona() {
+            Some(p) => println!("Active persona: \x1b[1m{}\x1b[0m", p.name),
+            None => println!("No active persona. Use /persona list to see options."),
+        },
+        "list" => {
+            let registry = rustant_core::PersonaRegistry::from_config(&config.personas);
+            let active = agent.active_persona().map(|p| p.name.as_str());
+            println!("\x1b[1mPersonas:\x1b[0m");
+            for p in registry.iter() {
+                let marker = if Some(p.name.as_str()) == active {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("  {} {:<20} {}", marker, p.name, p.description);
+            }
+        }
+        "off" | "none" => match switch_persona(agent, config, None) {
+            Ok(msg) => println!("\x1b[33m{}\x1b[0m", msg),
+            Err(e) => println!("\x1b[31m{}\x1b[0m", e),
+        },
+        name => match switch_persona(agent, config, Some(name)) {
+            Ok(msg) => println!("\x1b[32m{}\x1b[0m", msg),
+            Err(e) => println!("\x1b[31m{}\x1b[0m", e),
+        },
+    }
+}
+
+/// Run an /artifacts subcommand and return the text to show.
+///
+/// Shared by the REPL and TUI: `list` (default), `open <n>`, `reveal <n>`.
+/// Artifacts whose files were deleted are shown as stale instead of failing.
+pub(crate) fn artifacts_command(agent: &mut Agent, sub: &str, arg: &str) -> String {
+    for artifact in agent.artifacts_mut().iter_mut() {
+        artifact.refresh_stale();
+    }
+    let artifacts = agent.artifacts();
+    match sub {
+        "" | "list" => {
+            if artifacts.is_empty() {
+                return "No artifacts yet. Files, reports, and canvas items produced by tools appear here."
+                    .to_string();
+            }
+            let mut lines = vec![format!("Artifacts ({}):", artifacts.len())];
+            for (i, a) in artifacts.iter().enumerate() {
+                let location = a
+                    .path
+                    .as_ref()
+                    .map(|p| format!(" {}", p.display()))
+                    .unwrap_or_default();
+                let stale = if a.stale { " [stale]" } else { "" };
+                lines.push(format!(
+                    "  {:>2}. [{}] {} — {} at {}{}{}",
+                    i + 1,
+                    a.kind,
+                    a.title,
+                    a.source_tool,
+                    a.created_at.with_timezone(&chrono::Local).format("%H:%M"),
+                    location,
+                    stale
+                ));
+            }
+            lines.push("Use /artifacts open <n> or /artifacts reveal <n>.".to_string());
+            lines.join("\n")
+        }
+        "open" | "reveal" => {
+            let Some(artifact) = arg
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| artifacts.get(i))
+            else {
+                return format!(
+                    "Usage: /artifacts {} <n> (1-{})",
+                    sub,
+                    artifacts.len().max(1)
+                );
+            };
+            if let Some(canvas) = &artifact.canvas {
+                return format!(
+                    "Canvas item {} ({}) can be re-pushed from the dashboard \
+                     (POST /api/artifacts/{}/open). Content:\n{}",
+                    canvas.item_id, canvas.content_type, artifact.id, canvas.content
+                );
+            }
+            if artifact.stale {
+                return format!("'{}' is stale: the file no longer exists.", artifact.title);
+            }
+            match artifact.open(sub == "reveal") {
+                Ok(()) => format!(
+                    "{} {}",
+                    if sub == "reveal" {
+                        "Revealed"
+                    } else {
+                        "Opened"
+                    },
+                    artifact.title
+                ),
+                Err(e) => e,
+            }
+        }
+        _ => "Usage: /artifacts [list | open <n> | reveal <n>]".to_string(),
+    }
+}
+
 /// Handle the /why command -- show recent decision explanations.
 fn handle_why_command(index_str: &str, agent: &Agent) {
     let explanations = agent.recent_explanations();
@@ -4125,6 +5669,7 @@ fn handle_why_command(index_str: &str, agent: &Agent) {
         rustant_core::explanation::DecisionType::ParameterChoice { tool, .. } => tool.as_str(),
         rustant_core::explanation::DecisionType::ErrorRecovery { .. } => "N/A",
         rustant_core::explanation::DecisionType::TaskDecomposition { .. } => "N/A",
+        rustant_core::explanation::DecisionType::TaskRouting { .. } => "N/A",
     };
     println!("Tool: {}", tool_name);
     println!("Confidence: {:.2}", exp.confidence);
@@ -4604,4 +6149,32 @@ mod tests {
         std::fs::write(reminders_dir.join("index.json"), "{invalid").unwrap();
         handle_reminders_command("complete", "abc", tmp.path());
     }
+
+    #[test]
+    fn test_artifacts_command_lists_and_marks_stale() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut agent = Agent::new(
+            Arc::new(rustant_core::MockLlmProvider::new()),
+            AgentConfig::default(),
+            Arc::new(rustant_core::NoOpCallback),
+        );
+        assert!(artifacts_command(&mut agent, "", "").contains("No artifacts yet"));
+
+        let record = rustant_core::ArtifactRecord::from_artifact(
+            "pdf_generate",
+            &rustant_core::Artifact::FileCreated {
+                path: "report.pdf".into(),
+            },
+            Some(tmp.path()),
+            None,
+        )
+        .unwrap();
+        agent.set_artifacts(vec![record]);
+
+        let listed = artifacts_command(&mut agent, "list", "");
+        assert!(listed.contains("[report] report.pdf"));
+        assert!(listed.contains("[stale]"));
+        assert!(artifacts_command(&mut agent, "open", "1").contains("is stale"));
+        assert!(artifacts_command(&mut agent, "open", "9").starts_with("Usage"));
+    }
 }
@@ -6,6 +6,7 @@
 //! - `/` prefix slash command autocomplete (Tab to accept, Esc to dismiss)
 //! - Ctrl-C to clear line, Ctrl-D on empty line for EOF
 //! - Persistent history file at `.rustant/repl_history`
+//! - Spoken requests from an active voice session, submitted as typed lines
 
 use crate::slash::CommandRegistry;
 use crossterm::{
@@ -13,8 +14,10 @@ use crossterm::{
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
     terminal,
 };
+use rustant_core::VoiceTurn;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
 
 /// Persistent command history.
 pub struct InputHistory {
@@ -121,12 +124,31 @@ impl InputHistory {
 /// Completion state for slash commands.
 struct CompletionState {
     matches: Vec<String>,
+    /// Description shown after each match, parallel to `matches`.
+    descriptions: Vec<Option<String>>,
     selected: usize,
 }
 
+impl CompletionState {
+    /// Ghost text for the selected match: the command name plus its description.
+    fn hint(&self) -> String {
+        let name = &self.matches[self.selected];
+        match &self.descriptions[self.selected] {
+            Some(desc) => format!("{}  -- {}", name, desc),
+            None => name.clone(),
+        }
+    }
+}
+
 /// Interactive REPL input handler.
 pub struct ReplInput {
     history: InputHistory,
+    /// Prompt printed by the most recent `read_line`, reused on redraw.
+    prompt: String,
+    /// Requests from the voice command session.
+    voice_turns: Option<mpsc::Receiver<VoiceTurn>>,
+    /// The voice turn behind the line last returned, awaiting its reply.
+    voice_turn: Option<VoiceTurn>,
 }
 
 impl ReplInput {
@@ -134,9 +156,23 @@ impl ReplInput {
     pub fn new(workspace: &Path) -> Self {
         Self {
             history: InputHistory::new(workspace),
+            prompt: String::new(),
+            voice_turns: None,
+            voice_turn: None,
         }
     }
 
+    /// Accept spoken requests from `turns` while waiting for input.
+    pub fn set_voice_turns(&mut self, turns: mpsc::Receiver<VoiceTurn>) {
+        self.voice_turns = Some(turns);
+    }
+
+    /// The voice turn behind the line last returned by `read_line`, if it
+    /// was spoken rather than typed.
+    pub fn take_voice_turn(&mut self) -> Option<VoiceTurn> {
+        self.voice_turn.take()
+    }
+
     /// Read a line of input with interactive features.
     ///
     /// Returns `Some(line)` on Enter, `None` on Ctrl-D (EOF).
@@ -147,6 +183,7 @@ impl ReplInput {
         cmd_registry: &CommandRegistry,
     ) -> io::Result<Option<String>> {
         // Print prompt
+        self.prompt = prompt.to_string();
         print!("{}", prompt);
         io::stdout().flush()?;
 
@@ -168,6 +205,13 @@ impl ReplInput {
 
         loop {
             if !event::poll(std::time::Duration::from_millis(100))? {
+                if let Some(turn) = self.voice_turns.as_mut().and_then(|rx| rx.try_recv().ok()) {
+                    // A spoken request replaces whatever was being typed.
+                    let line = turn.text.clone();
+                    self.redraw_line(&line, line.len(), None)?;
+                    self.voice_turn = Some(turn);
+                    return Ok(Some(line));
+                }
                 continue;
             }
 
@@ -185,10 +229,8 @@ impl ReplInput {
                         return Ok(Some(String::new()));
                     }
                     // Ctrl-D on empty line: EOF
-                    (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
-                        if buffer.is_empty() {
-                            return Ok(None);
-                        }
+                    (KeyCode::Char('d'), KeyModifiers::CONTROL) if buffer.is_empty() => {
+                        return Ok(None);
                     }
                     // Tab: accept completion
                     (KeyCode::Tab, _) => {
@@ -210,11 +252,9 @@ impl ReplInput {
                         return Ok(Some(line));
                     }
                     // Escape: dismiss completion
-                    (KeyCode::Esc, _) => {
-                        if completion.is_some() {
-                            completion = None;
-                            self.redraw_line(&buffer, cursor_pos, None)?;
-                        }
+                    (KeyCode::Esc, _) if completion.is_some() => {
+                        completion = None;
+                        self.redraw_line(&buffer, cursor_pos, None)?;
                     }
                     // Up arrow: history or cycle completion
                     (KeyCode::Up, _) => {
@@ -222,8 +262,8 @@ impl ReplInput {
                             if comp.selected > 0 {
                                 comp.selected -= 1;
                             }
-                            let hint = Some(comp.matches[comp.selected].as_str());
-                            self.redraw_line(&buffer, cursor_pos, hint)?;
+                            let hint = comp.hint();
+                            self.redraw_line(&buffer, cursor_pos, Some(&hint))?;
                         } else if let Some(entry) = self.history.navigate_up(&buffer) {
                             buffer = entry.to_string();
                             cursor_pos = buffer.len();
@@ -236,8 +276,8 @@ impl ReplInput {
                             if comp.selected < comp.matches.len() - 1 {
                                 comp.selected += 1;
                             }
-                            let hint = Some(comp.matches[comp.selected].as_str());
-                            self.redraw_line(&buffer, cursor_pos, hint)?;
+                            let hint = comp.hint();
+                            self.redraw_line(&buffer, cursor_pos, Some(&hint))?;
                         } else if let Some(entry) = self.history.navigate_down() {
                             buffer = entry;
                             cursor_pos = buffer.len();
@@ -271,8 +311,8 @@ impl ReplInput {
                         }
                         // Update completion
                         completion = self.update_completion(&buffer, cmd_registry);
-                        let hint = completion.as_ref().map(|c| c.matches[c.selected].as_str());
-                        self.redraw_line(&buffer, cursor_pos, hint)?;
+                        let hint = completion.as_ref().map(CompletionState::hint);
+                        self.redraw_line(&buffer, cursor_pos, hint.as_deref())?;
                     }
                     // Home
                     (KeyCode::Home, _) => {
@@ -293,8 +333,8 @@ impl ReplInput {
 
                         // Update completions for slash commands
                         completion = self.update_completion(&buffer, cmd_registry);
-                        let hint = completion.as_ref().map(|c| c.matches[c.selected].as_str());
-                        self.redraw_line(&buffer, cursor_pos, hint)?;
+                        let hint = completion.as_ref().map(CompletionState::hint);
+                        self.redraw_line(&buffer, cursor_pos, hint.as_deref())?;
                     }
                     _ => {}
                 }
@@ -320,8 +360,13 @@ impl ReplInput {
         if matches.is_empty() || (matches.len() == 1 && matches[0] == buffer) {
             return None;
         }
+        let descriptions = matches
+            .iter()
+            .map(|m| cmd_registry.describe(m).map(str::to_string))
+            .collect();
         Some(CompletionState {
             matches,
+            descriptions,
             selected: 0,
         })
     }
@@ -337,7 +382,7 @@ impl ReplInput {
         // Move to start of line and clear
         write!(stdout, "\r\x1b[2K")?;
         // Reprint prompt
-        write!(stdout, "\x1b[1;34m> \x1b[0m")?;
+        write!(stdout, "{}", self.prompt)?;
         // Print buffer
         write!(stdout, "{}", buffer)?;
         // Print ghost completion text if available
@@ -348,7 +393,7 @@ impl ReplInput {
             let suffix = &hint[buffer.len()..];
             write!(stdout, "\x1b[90m{}\x1b[0m", suffix)?;
             // Move cursor back to actual position
-            let hint_len = suffix.len();
+            let hint_len = suffix.chars().count();
             if hint_len > 0 {
                 write!(stdout, "{}", cursor::MoveLeft(hint_len as u16))?;
             }
@@ -0,0 +1,887 @@
+//! Incremental markdown rendering for streamed REPL responses.
+//!
+//! [`StreamRenderer`] receives a response chunk by chunk and returns only the
+//! new terminal output. A cursor tracks how much of the current line has been
+//! printed, so nothing is ever emitted twice:
+//! - Complete lines are rendered as soon as their newline arrives
+//! - A partial prose line is emitted up to its last word boundary that is
+//!   outside any open inline span (`` `code` ``, `**bold**`, `*italic*`)
+//! - Fenced code is highlighted line by line once the fence's language tag
+//!   is known, falling back to plain code if highlighting fails mid-block
+//! - Tables are drawn row by row; a column widens only when a row needs it,
+//!   and cells wrap to fit the terminal width
+//!
+//! When stdout is not a terminal, or is narrower than [`MIN_WIDTH`] columns,
+//! the raw text is passed through unchanged.
+
+use std::io::IsTerminal;
+use std::sync::LazyLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+use unicode_width::UnicodeWidthStr;
+
+/// Narrowest terminal that gets styled output.
+const MIN_WIDTH: usize = 40;
+/// Widest horizontal rule.
+const MAX_RULE_WIDTH: usize = 80;
+/// syntect theme for fenced code.
+const CODE_THEME: &str = "base16-ocean.dark";
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const ITALIC: &str = "\x1b[3m";
+const DIM: &str = "\x1b[90m";
+const ACCENT: &str = "\x1b[36m";
+const HEADING: &str = "\x1b[1;36m";
+const TITLE: &str = "\x1b[1;4;36m";
+
+static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+
+/// Renders one streamed response. Create a new renderer per response.
+pub(crate) struct StreamRenderer {
+    styled: bool,
+    width: usize,
+    /// The current line, without its newline.
+    line: String,
+    /// Bytes of `line` already printed.
+    emitted: usize,
+    /// How the current line was classified, once its marker is printed.
+    current: Option<Prefix>,
+    block: Block,
+}
+
+enum Block {
+    Text,
+    Code {
+        fence: String,
+        highlighter: Option<HighlightLines<'static>>,
+    },
+    /// A `|` line held until the next line shows whether it heads a table.
+    TableHeader(String),
+    Table(Table),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Blank,
+    Fence,
+    TableRow,
+    Rule,
+    Heading(usize),
+    Bullet,
+    Ordered,
+    Paragraph,
+}
+
+/// A classified line: its kind, nesting, and where its text starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Prefix {
+    kind: LineKind,
+    /// Block quote depth.
+    quote: usize,
+    /// Leading spaces after any quote markers.
+    indent: usize,
+    /// Byte offset of the list marker or first character.
+    start: usize,
+    /// Byte offset of the text after any marker.
+    body: usize,
+}
+
+impl Prefix {
+    /// Plain text starting at the beginning of the line.
+    fn paragraph() -> Self {
+        Self {
+            kind: LineKind::Paragraph,
+            quote: 0,
+            indent: 0,
+            start: 0,
+            body: 0,
+        }
+    }
+
+    /// Whether a partial line of this kind can be printed before it ends.
+    fn streams(&self) -> bool {
+        matches!(
+            self.kind,
+            LineKind::Heading(_) | LineKind::Bullet | LineKind::Ordered | LineKind::Paragraph
+        )
+    }
+}
+
+impl StreamRenderer {
+    /// A renderer for stdout, styled when it is a wide enough terminal.
+    pub(crate) fn for_stdout() -> Self {
+        let width = crossterm::terminal::size()
+            .map(|(cols, _)| cols as usize)
+            .unwrap_or(0);
+        Self::new(std::io::stdout().is_terminal(), width)
+    }
+
+    pub(crate) fn new(terminal: bool, width: usize) -> Self {
+        Self {
+            styled: terminal && width >= MIN_WIDTH,
+            width,
+            line: String::new(),
+            emitted: 0,
+            current: None,
+            block: Block::Text,
+        }
+    }
+
+    /// Add a chunk and return the output it makes ready.
+    pub(crate) fn push(&mut self, chunk: &str) -> String {
+        if !self.styled {
+            return chunk.to_string();
+        }
+        let mut out = String::new();
+        for piece in chunk.split_inclusive('\n') {
+            match piece.strip_suffix('\n') {
+                Some(text) => {
+                    self.line.push_str(text);
+                    self.end_line(&mut out);
+                }
+                None => self.line.push_str(piece),
+            }
+        }
+        self.stream_partial(&mut out);
+        out
+    }
+
+    /// Render whatever is still held. The result has no trailing newline.
+    pub(crate) fn finish(&mut self) -> String {
+        let mut out = String::new();
+        if !self.styled {
+            return out;
+        }
+        if !self.line.is_empty() {
+            self.end_line(&mut out);
+        }
+        if let Block::TableHeader(header) = std::mem::replace(&mut self.block, Block::Text) {
+            self.text_line(&header, 0, Some(Prefix::paragraph()), &mut out);
+        }
+        if out.ends_with('\n') {
+            out.pop();
+        }
+        out
+    }
+
+    /// Print as much of the partial line as is already certain.
+    fn stream_partial(&mut self, out: &mut String) {
+        if self.line.is_empty() {
+            return;
+        }
+        if matches!(self.block, Block::Table(_)) && self.current.is_none() {
+            match classify(&self.line, false) {
+                Some(p) if p.kind != LineKind::TableRow => self.block = Block::Text,
+                _ => return,
+            }
+        }
+        if !matches!(self.block, Block::Text) {
+            return;
+        }
+        let prefix = match &self.current {
+            Some(prefix) => prefix.clone(),
+            None => match classify(&self.line, false) {
+                Some(prefix) if prefix.streams() => prefix,
+                _ => return,
+            },
+        };
+        if self.current.is_none() {
+            out.push_str(&marker(&prefix, &self.line));
+            self.emitted = prefix.body;
+            self.current = Some(prefix.clone());
+        }
+        let start = self.emitted.max(prefix.body);
+        let safe = safe_boundary(&self.line[start..]);
+        if safe > 0 {
+            out.push_str(&render_inline(
+                &self.line[start..start + safe],
+                base_style(&prefix),
+            ));
+            self.emitted = start + safe;
+        }
+    }
+
+    /// Render the rest of the now complete line.
+    fn end_line(&mut self, out: &mut String) {
+        let mut line = std::mem::take(&mut self.line);
+        if line.ends_with('\r') {
+            line.pop();
+        }
+        let emitted = std::mem::take(&mut self.emitted);
+        let current = self.current.take();
+
+        if let Block::Code { fence, highlighter } = &mut self.block {
+            if !is_closing_fence(&line, fence) {
+                out.push_str(&highlight(highlighter, &line));
+                out.push('\n');
+                return;
+            }
+            out.push_str(&format!("{}{}{}\n", DIM, line.trim(), RESET));
+            self.block = Block::Text;
+            return;
+        }
+        if let Block::TableHeader(header) = &self.block {
+            let header = header.clone();
+            self.block = Block::Text;
+            if let Some(align) = delimiter_row(&line) {
+                let mut table = Table::new(align, self.width);
+                out.push_str(&table.render_row(&split_row(&header), true));
+                out.push_str(&format!("{}{}{}\n", DIM, table.separator(), RESET));
+                self.block = Block::Table(table);
+                return;
+            }
+            self.text_line(&header, 0, Some(Prefix::paragraph()), out);
+        }
+        if let Block::Table(table) = &mut self.block {
+            if classify(&line, true).is_some_and(|p| p.kind == LineKind::TableRow) {
+                out.push_str(&table.render_row(&split_row(&line), false));
+                return;
+            }
+            self.block = Block::Text;
+        }
+        self.text_line(&line, emitted, current, out);
+    }
+
+    /// Render a complete line outside code blocks and tables. `emitted`
+    /// bytes of it were already printed under the `current` classification.
+    fn text_line(&mut self, line: &str, emitted: usize, current: Option<Prefix>, out: &mut String) {
+        let printed_marker = current.is_some();
+        let prefix = current
+            .or_else(|| classify(line, true))
+            .unwrap_or_else(Prefix::paragraph);
+        match prefix.kind {
+            LineKind::Fence => {
+                let content = line.trim();
+                let run = content.len() - content.trim_start_matches(['`', '~']).len();
+                self.block = Block::Code {
+                    fence: content[..run].to_string(),
+                    highlighter: code_highlighter(content[run..].trim()),
+                };
+                out.push_str(&format!("{}{}{}\n", DIM, content, RESET));
+            }
+            LineKind::TableRow => self.block = Block::TableHeader(line.to_string()),
+            LineKind::Rule => {
+                let rule = "─".repeat(self.width.min(MAX_RULE_WIDTH));
+                out.push_str(&format!("{}{}{}\n", DIM, rule, RESET));
+            }
+            LineKind::Blank => {
+                out.push_str(&quote_bars(prefix.quote));
+                out.push('\n');
+            }
+            _ => {
+                if !printed_marker {
+                    out.push_str(&marker(&prefix, line));
+                }
+                let start = emitted.max(prefix.body);
+                out.push_str(&render_inline(&line[start..], base_style(&prefix)));
+                out.push('\n');
+            }
+        }
+    }
+}
+
+/// Classify a line by its prefix. Returns `None` while a partial line could
+/// still become more than one kind; complete lines always classify.
+fn classify(line: &str, complete: bool) -> Option<Prefix> {
+    let mut rest = line;
+    let mut quote = 0;
+    while let Some(after) = rest.trim_start_matches(' ').strip_prefix('>') {
+        quote += 1;
+        rest = after;
+    }
+    let content = rest.trim_start_matches(' ');
+    let spaces = rest.len() - content.len();
+    let start = line.len() - content.len();
+    let prefix = |kind, marker_len: usize| {
+        Some(Prefix {
+            kind,
+            quote,
+            indent: if quote > 0 {
+                spaces.saturating_sub(1)
+            } else {
+                spaces
+            },
+            start,
+            body: start + marker_len,
+        })
+    };
+    let undecided = |kind| if complete { prefix(kind, 0) } else { None };
+
+    let Some(first) = content.chars().next() else {
+        return undecided(LineKind::Blank);
+    };
+    match first {
+        '`' | '~' if quote == 0 => {
+            let run = content.len() - content.trim_start_matches(first).len();
+            if run >= 3 {
+                prefix(LineKind::Fence, 0)
+            } else if run == content.len() {
+                undecided(LineKind::Paragraph)
+            } else {
+                prefix(LineKind::Paragraph, 0)
+            }
+        }
+        '|' if quote == 0 && spaces < 4 => prefix(LineKind::TableRow, 0),
+        '#' => {
+            let hashes = content.len() - content.trim_start_matches('#').len();
+            let after = &content[hashes..];
+            if hashes > 6 {
+                prefix(LineKind::Paragraph, 0)
+            } else if after.starts_with(' ') {
+                prefix(LineKind::Heading(hashes), hashes + 1)
+            } else if after.is_empty() {
+                undecided(LineKind::Heading(hashes))
+            } else {
+                prefix(LineKind::Paragraph, 0)
+            }
+        }
+        '-' | '*' | '+' | '_' => {
+            if complete && is_rule(content) {
+                prefix(LineKind::Rule, 0)
+            } else if first != '_' && content[1..].starts_with(' ') {
+                prefix(LineKind::Bullet, 2)
+            } else if content.chars().all(|c| c == first || c == ' ') {
+                undecided(LineKind::Paragraph)
+            } else {
+                prefix(LineKind::Paragraph, 0)
+            }
+        }
+        '0'..='9' => {
+            let digits = content.len()
+                - content
+                    .trim_start_matches(|c: char| c.is_ascii_digit())
+                    .len();
+            let after = &content[digits..];
+            if digits > 9 {
+                prefix(LineKind::Paragraph, 0)
+            } else if after.is_empty() {
+                undecided(LineKind::Paragraph)
+            } else if let Some(after) = after.strip_prefix(['.', ')']) {
+                if after.starts_with(' ') {
+                    prefix(LineKind::Ordered, digits + 2)
+                } else if after.is_empty() {
+                    undecided(LineKind::Paragraph)
+                } else {
+                    prefix(LineKind::Paragraph, 0)
+                }
+            } else {
+                prefix(LineKind::Paragraph, 0)
+            }
+        }
+        _ => prefix(LineKind::Paragraph, 0),
+    }
+}
+
+/// `---`, `***` or `___`, optionally spaced.
+fn is_rule(content: &str) -> bool {
+    let mut marks = content.chars().filter(|c| *c != ' ');
+    let Some(first) = marks.next() else {
+        return false;
+    };
+    matches!(first, '-' | '*' | '_')
+        && content.matches(first).count() >= 3
+        && marks.all(|c| c == first)
+}
+
+fn quote_bars(depth: usize) -> String {
+    format!("{}│{} ", DIM, RESET).repeat(depth)
+}
+
+/// Quote bars, indentation and list marker for a line.
+fn marker(prefix: &Prefix, line: &str) -> String {
+    let mut out = quote_bars(prefix.quote);
+    let nesting = "  ".repeat(prefix.indent / 2);
+    match prefix.kind {
+        LineKind::Bullet => {
+            let bullet = ["•", "◦", "▪"][(prefix.indent / 2) % 3];
+            out.push_str(&format!("{}{}{}{} ", nesting, ACCENT, bullet, RESET));
+        }
+        LineKind::Ordered => {
+            let number = line[prefix.start..prefix.body].trim_end();
+            out.push_str(&format!("{}{}{}{} ", nesting, ACCENT, number, RESET));
+        }
+        LineKind::Paragraph => out.push_str(&" ".repeat(prefix.indent)),
+        _ => {}
+    }
+    out
+}
+
+fn base_style(prefix: &Prefix) -> &'static str {
+    match prefix.kind {
+        LineKind::Heading(1) => TITLE,
+        LineKind::Heading(_) => HEADING,
+        _ if prefix.quote > 0 => ITALIC,
+        _ => "",
+    }
+}
+
+/// Length of the longest prefix of `text` that ends after whitespace with
+/// every inline span closed. Text past it may still change meaning.
+fn safe_boundary(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut safe = 0;
+    let mut code: Option<usize> = None;
+    let (mut bold, mut italic) = (false, false);
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if byte == b'`' {
+            let run = bytes[i..].iter().take_while(|b| **b == b'`').count();
+            if i + run == bytes.len() {
+                return safe;
+            }
+            code = match code {
+                None => Some(run),
+                Some(open) if open == run => None,
+                open => open,
+            };
+            i += run;
+            continue;
+        }
+        if code.is_some() {
+            i += 1;
+            continue;
+        }
+        if byte == b'*' {
+            let double = bytes.get(i + 1) == Some(&b'*');
+            let len = if double { 2 } else { 1 };
+            let open = if double { &mut bold } else { &mut italic };
+            if *open {
+                if i > 0 && !bytes[i - 1].is_ascii_whitespace() {
+                    *open = false;
+                }
+            } else {
+                match bytes.get(i + len) {
+                    None => return safe,
+                    Some(next) if !next.is_ascii_whitespace() => *open = true,
+                    Some(_) => {}
+                }
+            }
+            i += len;
+            continue;
+        }
+        if byte.is_ascii_whitespace() && !bold && !italic {
+            safe = i + 1;
+        }
+        i += 1;
+    }
+    safe
+}
+
+/// Render inline code, bold and italic. Unmatched markers stay literal.
+fn render_inline(text: &str, base: &str) -> String {
+    let mut out = String::from(base);
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with('`') {
+            let run = rest.len() - rest.trim_start_matches('`').len();
+            let fence = &rest[..run];
+            if let Some(end) = rest[run..].find(fence) {
+                push_span(&mut out, ACCENT, &rest[run..run + end], base);
+                i += run + end + run;
+            } else {
+                out.push_str(fence);
+                i += run;
+            }
+            continue;
+        }
+        if let Some((marker, style)) = [("**", BOLD), ("*", ITALIC)]
+            .into_iter()
+            .find(|(m, _)| rest.starts_with(m))
+        {
+            let inner = &rest[marker.len()..];
+            if inner.starts_with(|c: char| !c.is_whitespace())
+                && let Some(end) = find_closer(inner, marker)
+            {
+                push_span(&mut out, style, &inner[..end], base);
+                i += marker.len() * 2 + end;
+            } else {
+                out.push_str(marker);
+                i += marker.len();
+            }
+            continue;
+        }
+        let Some(ch) = rest.chars().next() else {
+            break;
+        };
+        out.push(ch);
+        i += ch.len_utf8();
+    }
+    if !base.is_empty() {
+        out.push_str(RESET);
+    }
+    out
+}
+
+/// Offset of the marker closing a span, which must follow a non-space.
+fn find_closer(inner: &str, marker: &str) -> Option<usize> {
+    inner
+        .match_indices(marker)
+        .map(|(pos, _)| pos)
+        .find(|&pos| pos > 0 && !inner[..pos].ends_with(char::is_whitespace))
+}
+
+fn push_span(out: &mut String, style: &str, text: &str, base: &str) {
+    out.push_str(style);
+    out.push_str(text);
+    out.push_str(RESET);
+    out.push_str(base);
+}
+
+fn code_highlighter(lang: &str) -> Option<HighlightLines<'static>> {
+    if lang.is_empty() {
+        return None;
+    }
+    let syntax = SYNTAX_SET.find_syntax_by_token(lang)?;
+    let theme = THEME_SET
+        .themes
+        .get(CODE_THEME)
+        .or_else(|| THEME_SET.themes.values().next())?;
+    Some(HighlightLines::new(syntax, theme))
+}
+
+/// Highlight one code line. If highlighting fails, the rest of the block is
+/// shown as plain code.
+fn highlight(highlighter: &mut Option<HighlightLines<'static>>, line: &str) -> String {
+    if let Some(lines) = highlighter {
+        let text = format!("{}\n", line);
+        match lines.highlight_line(&text, &SYNTAX_SET) {
+            Ok(ranges) => {
+                let mut out = String::new();
+                for (style, piece) in ranges {
+                    let piece = piece.trim_end_matches('\n');
+                    if !piece.is_empty() {
+                        let fg = style.foreground;
+                        out.push_str(&format!("\x1b[38;2;{};{};{}m{}", fg.r, fg.g, fg.b, piece));
+                    }
+                }
+                out.push_str(RESET);
+                return out;
+            }
+            Err(_) => *highlighter = None,
+        }
+    }
+    format!("{}{}{}", ACCENT, line, RESET)
+}
+
+fn is_closing_fence(line: &str, fence: &str) -> bool {
+    let content = line.trim();
+    let Some(first) = fence.chars().next() else {
+        return false;
+    };
+    content.len() >= fence.len() && content.chars().all(|c| c == first)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// Column alignments from a `|---|:--:|` row, if the line is one.
+fn delimiter_row(line: &str) -> Option<Vec<Align>> {
+    if !line.trim_start().starts_with('|') {
+        return None;
+    }
+    split_row(line)
+        .iter()
+        .map(|cell| {
+            let dashes = cell.trim_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => Align::Center,
+                (false, true) => Align::Right,
+                _ => Align::Left,
+            })
+        })
+        .collect()
+}
+
+/// Cell texts of a table row, with inline markers removed.
+fn split_row(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let inner = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+    inner
+        .split('|')
+        .map(|cell| cell.trim().replace("**", "").replace('`', ""))
+        .collect()
+}
+
+/// A table being drawn row by row.
+struct Table {
+    widths: Vec<usize>,
+    align: Vec<Align>,
+    max_width: usize,
+}
+
+impl Table {
+    const MIN_COLUMN: usize = 3;
+    const GAP: &str = " │ ";
+
+    fn new(align: Vec<Align>, max_width: usize) -> Self {
+        Self {
+            widths: vec![Self::MIN_COLUMN; align.len()],
+            align,
+            max_width,
+        }
+    }
+
+    /// Render a row, widening columns first if a cell needs more room.
+    fn render_row(&mut self, cells: &[String], header: bool) -> String {
+        if cells.len() > self.widths.len() {
+            self.widths.resize(cells.len(), Self::MIN_COLUMN);
+        }
+        let mut grew = false;
+        for (width, cell) in self.widths.iter_mut().zip(cells) {
+            if cell.width() > *width {
+                *width = cell.width();
+                grew = true;
+            }
+        }
+        if grew {
+            self.fit();
+        }
+
+        let wrapped: Vec<Vec<String>> = self
+            .widths
+            .iter()
+            .enumerate()
+            .map(|(i, width)| wrap(cells.get(i).map_or("", String::as_str), *width))
+            .collect();
+        let height = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+        let mut out = String::new();
+        for row in 0..height {
+            let line: Vec<String> = wrapped
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    let text = cell.get(row).map_or("", String::as_str);
+                    let align = self.align.get(i).copied().unwrap_or(Align::Left);
+                    let padded = pad(text, self.widths[i], align);
+                    if header {
+                        format!("{}{}{}", BOLD, padded, RESET)
+                    } else {
+                        padded
+                    }
+                })
+                .collect();
+            out.push_str(
+                line.join(&format!("{}{}{}", DIM, Self::GAP, RESET))
+                    .trim_end(),
+            );
+            out.push('\n');
+        }
+        out
+    }
+
+    fn separator(&self) -> String {
+        self.widths
+            .iter()
+            .map(|w| "─".repeat(*w))
+            .collect::<Vec<_>>()
+            .join("─┼─")
+    }
+
+    /// Narrow the widest columns until the table fits the terminal.
+    fn fit(&mut self) {
+        let gaps = Self::GAP.width() * self.widths.len().saturating_sub(1);
+        while self.widths.iter().sum::<usize>() + gaps > self.max_width {
+            let Some(widest) = self
+                .widths
+                .iter_mut()
+                .max_by_key(|w| **w)
+                .filter(|w| **w > Self::MIN_COLUMN)
+            else {
+                break;
+            };
+            *widest -= 1;
+        }
+    }
+}
+
+/// Word-wrap `text` to `width` columns, splitting words that do not fit.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for word in text.split_whitespace() {
+        let mut word = word;
+        while !word.is_empty() {
+            let Some(current) = lines.last_mut() else {
+                break;
+            };
+            let used = current.width();
+            let space = usize::from(used > 0);
+            if used + space + word.width() <= width {
+                if space == 1 {
+                    current.push(' ');
+                }
+                current.push_str(word);
+                break;
+            }
+            if used > 0 {
+                lines.push(String::new());
+                continue;
+            }
+            let split = split_at_width(word, width);
+            current.push_str(&word[..split]);
+            word = &word[split..];
+            lines.push(String::new());
+        }
+    }
+    if lines.len() > 1 && lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Byte offset of the longest prefix of `word` that fits in `width`
+/// columns, and at least one character.
+fn split_at_width(word: &str, width: usize) -> usize {
+    let mut used = 0;
+    for (i, ch) in word.char_indices() {
+        let w = unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
+        if i > 0 && used + w > width {
+            return i;
+        }
+        used += w;
+    }
+    word.len()
+}
+
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let room = width.saturating_sub(text.width());
+    match align {
+        Align::Left => format!("{}{}", text, " ".repeat(room)),
+        Align::Right => format!("{}{}", " ".repeat(room), text),
+        Align::Center => format!(
+            "{}{}{}",
+            " ".repeat(room / 2),
+            text,
+            " ".repeat(room - room / 2)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A realistic streamed answer, as an LLM would send it.
+    const RESPONSE: &str = "## Summary\n\nThe `parse_config` function **fails** when the \
+file is *empty*:\n\n- Reads the file\n  - Trims `whitespace`\n- Returns an error\n\n\
+1. Check the path\n2. Retry\n\n> Note: **never** edit\n> > nested\n\n\
+```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+| Name | Size |\n|:-----|-----:|\n| a.rs | 12 |\n| main_module.rs | 3400 |\n\n---\nDone.";
+
+    /// The final frame of [`RESPONSE`] with styling removed.
+    const FRAME: &str = "Summary\n\nThe parse_config function fails when the file is empty:\n\n\
+• Reads the file\n  ◦ Trims whitespace\n• Returns an error\n\n\
+1. Check the path\n2. Retry\n\n│ Note: never edit\n│ │ nested\n\n\
+```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+Name │ Size\n─────┼─────\na.rs │   12\nmain_module.rs │ 3400\n\n\
+────────────────────────────────────────────────────────────\nDone.";
+
+    fn render_chunked(text: &str, chunk_chars: usize) -> String {
+        let mut renderer = StreamRenderer::new(true, 60);
+        let chars: Vec<char> = text.chars().collect();
+        let mut out = String::new();
+        for chunk in chars.chunks(chunk_chars) {
+            out.push_str(&renderer.push(&chunk.iter().collect::<String>()));
+        }
+        out.push_str(&renderer.finish());
+        out
+    }
+
+    fn strip_ansi(text: &str) -> String {
+        let mut out = String::new();
+        let mut chars = text.chars();
+        while let Some(ch) = chars.next() {
+            if ch == '\x1b' {
+                for c in chars.by_ref() {
+                    if c == 'm' {
+                        break;
+                    }
+                }
+            } else {
+                out.push(ch);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_chunked_response_renders_final_frame() {
+        for chunk_chars in [1, 2, 3, 7, 16, RESPONSE.len()] {
+            assert_eq!(
+                strip_ansi(&render_chunked(RESPONSE, chunk_chars)),
+                FRAME,
+                "chunk size {}",
+                chunk_chars
+            );
+        }
+    }
+
+    #[test]
+    fn test_partial_line_streams_only_closed_spans() {
+        let mut renderer = StreamRenderer::new(true, 80);
+        assert_eq!(strip_ansi(&renderer.push("Use the `car")), "Use the ");
+        assert_eq!(
+            strip_ansi(&renderer.push("go test` command")),
+            "cargo test "
+        );
+        assert_eq!(strip_ansi(&renderer.push(" **now")), "command ");
+        assert_eq!(strip_ansi(&renderer.push("**\n")), "now\n");
+        let out = renderer.push("- ");
+        assert_eq!(strip_ansi(&out), "• ");
+        assert_eq!(strip_ansi(&renderer.finish()), "");
+    }
+
+    #[test]
+    fn test_code_block_highlighted_line_by_line() {
+        let mut renderer = StreamRenderer::new(true, 80);
+        assert_eq!(strip_ansi(&renderer.push("```rust\nfn ma")), "```rust\n");
+        let line = renderer.push("in() {}\n");
+        assert!(line.contains("\x1b[38;2;"), "{:?}", line);
+        assert_eq!(strip_ansi(&line), "fn main() {}\n");
+
+        let mut renderer = StreamRenderer::new(true, 80);
+        let out = renderer.push("```not-a-language\nx = 1\n```\n");
+        assert_eq!(strip_ansi(&out), "```not-a-language\nx = 1\n```\n");
+        assert!(!out.contains("\x1b[38;2;"));
+    }
+
+    #[test]
+    fn test_table_widens_columns_only_when_forced_and_fits_width() {
+        let mut renderer = StreamRenderer::new(true, 40);
+        let out = renderer.push("| Key | Value |\n|---|---|\n| a | b |\n");
+        assert_eq!(strip_ansi(&out), "Key │ Value\n────┼──────\na   │ b\n");
+        let out =
+            renderer.push("| long_key | a value that is much too long for the terminal width |\n");
+        assert!(strip_ansi(&out).lines().all(|l| l.width() <= 40));
+        assert!(strip_ansi(&out).lines().count() > 1);
+        assert!(strip_ansi(&out).starts_with("long_key │ a value"));
+
+        // A lone `|` line without a delimiter row is plain text.
+        let mut renderer = StreamRenderer::new(true, 40);
+        let out = renderer.push("| not a table\nnext\n");
+        assert_eq!(strip_ansi(&out), "| not a table\nnext\n");
+    }
+
+    #[test]
+    fn test_plain_passthrough_when_not_terminal_or_narrow() {
+        for mut renderer in [
+            StreamRenderer::new(false, 120),
+            StreamRenderer::new(true, 20),
+        ] {
+            assert_eq!(renderer.push("# Title\n**x**"), "# Title\n**x**");
+            assert_eq!(renderer.finish(), "");
+        }
+    }
+}
@@ -1,4 +1,4 @@
-//! Interactive provider setup wizard.
+//! Interactive setup wizard.
 //!
 //! Walks the user through configuring an LLM provider:
 //! 1. Select a provider (OpenAI, Anthropic, Custom)
@@ -8,11 +8,19 @@
 //! 5. Select a model from the fetched list
 //! 6. Store the key in the OS credential store
 //! 7. Update the workspace configuration file
-
-use dialoguer::{Input, Password, Select};
-use rustant_core::credentials::{CredentialStore, KeyringCredentialStore};
+//!
+//! Then offers optional, skippable sections for messaging channels (reusing
+//! the `channel_setup` flows plus a live test message) and voice (microphone
+//! check, wake word vs push-to-talk, TTS test), and ends with a summary that
+//! writes those sections to config. Any section can be re-run on its own with
+//! `rustant setup --section <provider|channels|voice>`.
+
+use crate::channel_setup::{self, ChannelSetup};
+use dialoguer::{Input, MultiSelect, Password, Select};
+use rustant_core::config::{VoiceActivation, VoiceConfig};
+use rustant_core::credentials::{CredentialStore, configured_store};
 use rustant_core::providers::models::{ModelInfo, list_models};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// A provider option presented during the setup wizard.
 #[derive(Debug, Clone)]
@@ -64,15 +72,11 @@ pub fn validate_api_key_format(provider: &str, key: &str) -> Result<(), String>
         return Err("API key cannot be empty".to_string());
     }
     match provider {
-        "openai" => {
-            if !key.starts_with("sk-") {
-                return Err("OpenAI API keys typically start with 'sk-'".to_string());
-            }
+        "openai" if !key.starts_with("sk-") => {
+            return Err("OpenAI API keys typically start with 'sk-'".to_string());
         }
-        "anthropic" => {
-            if !key.starts_with("sk-ant-") {
-                return Err("Anthropic API keys typically start with 'sk-ant-'".to_string());
-            }
+        "anthropic" if !key.starts_with("sk-ant-") => {
+            return Err("Anthropic API keys typically start with 'sk-ant-'".to_string());
         }
         _ => {} // No format validation for custom providers
     }
@@ -139,11 +143,415 @@ pub fn detect_env_api_keys() -> Vec<(String, String)> {
     found
 }
 
-/// Run the interactive provider setup wizard.
+/// A section of the setup wizard that can be run on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupSection {
+    Provider,
+    Channels,
+    Voice,
+}
+
+impl SetupSection {
+    /// All sections, in wizard order.
+    pub const ALL: [SetupSection; 3] = [Self::Provider, Self::Channels, Self::Voice];
+
+    /// Parse a `--section` value.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "provider" | "llm" => Some(Self::Provider),
+            "channels" | "channel" => Some(Self::Channels),
+            "voice" => Some(Self::Voice),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Provider => "provider",
+            Self::Channels => "channels",
+            Self::Voice => "voice",
+        }
+    }
+}
+
+/// Channels offered by the wizard. Their configs hold secrets only as
+/// `SecretRef`s; the remaining channels are available via `rustant channel setup`.
+const WIZARD_CHANNELS: &[&str] = &["slack", "telegram", "email"];
+
+/// Everything collected by the optional wizard sections, written to config
+/// in the final summary.
+#[derive(Debug, Default)]
+pub struct SetupPlan {
+    /// Channels that passed (or were kept despite) validation.
+    pub channels: Vec<ChannelSetup>,
+    /// Voice settings, if the voice section was completed.
+    pub voice: Option<VoiceConfig>,
+}
+
+impl SetupPlan {
+    /// Write the collected sections to `.rustant/config.toml`. Only the
+    /// configured channels and the `[voice]` section are replaced; all other
+    /// settings are preserved. Returns the config path if anything was written.
+    pub fn apply(&self, workspace: &Path) -> anyhow::Result<Option<PathBuf>> {
+        let mut written = None;
+        for channel in &self.channels {
+            written = Some(channel_setup::save_channel_setup(workspace, channel)?);
+        }
+        if let Some(voice) = &self.voice {
+            written = Some(rustant_core::config::update_config_section(
+                workspace,
+                "voice",
+                toml::Value::try_from(voice)?,
+            )?);
+        }
+        Ok(written)
+    }
+}
+
+/// Run the full setup wizard: provider, then the optional channel and voice
+/// sections, then a summary.
+pub async fn run_setup(workspace: &Path) -> anyhow::Result<()> {
+    run_sections(workspace, &SetupSection::ALL).await
+}
+
+/// Run the wizard for `rustant setup [--section <name>]`.
+pub async fn run_setup_section(workspace: &Path, section: Option<&str>) -> anyhow::Result<()> {
+    match section {
+        None => run_setup(workspace).await,
+        Some(name) => {
+            let section = SetupSection::from_name(name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown setup section '{}'. Valid sections: provider, channels, voice",
+                    name
+                )
+            })?;
+            run_sections(workspace, &[section]).await
+        }
+    }
+}
+
+async fn run_sections(workspace: &Path, sections: &[SetupSection]) -> anyhow::Result<()> {
+    let full_wizard = sections.len() > 1;
+    let mut provider_done = false;
+    let mut plan = SetupPlan::default();
+
+    for &section in sections {
+        match section {
+            SetupSection::Provider if !full_wizard => {
+                run_provider_setup(workspace).await?;
+                provider_done = true;
+            }
+            SetupSection::Provider => loop {
+                match run_provider_setup(workspace).await {
+                    Ok(()) => {
+                        provider_done = true;
+                        break;
+                    }
+                    Err(e) => {
+                        println!("  Provider setup failed: {}", e);
+                        if !retry_or_skip()? {
+                            break;
+                        }
+                    }
+                }
+            },
+            SetupSection::Channels | SetupSection::Voice => {
+                if full_wizard && !confirm_section(section)? {
+                    println!(
+                        "  Skipped. Run `rustant setup --section {}` any time.\n",
+                        section.name()
+                    );
+                    continue;
+                }
+                if section == SetupSection::Channels {
+                    setup_channels(&mut plan).await?;
+                } else {
+                    plan.voice = setup_voice(workspace).await?;
+                }
+            }
+        }
+    }
+
+    if sections.contains(&SetupSection::Channels) || sections.contains(&SetupSection::Voice) {
+        print_summary(sections, provider_done, &plan);
+        match plan.apply(workspace)? {
+            Some(path) => println!("\n  Configuration saved to {}\n", path.display()),
+            None => println!("\n  Nothing to save.\n"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Ask whether to run an optional section now.
+fn confirm_section(section: SetupSection) -> anyhow::Result<bool> {
+    let prompt = match section {
+        SetupSection::Channels => "Set up messaging channels (Slack, Telegram, email) now?",
+        _ => "Set up voice mode now?",
+    };
+    Ok(dialoguer::Confirm::new()
+        .with_prompt(prompt)
+        .default(false)
+        .interact()?)
+}
+
+/// After a failed step, ask whether to retry it. Returns `false` to skip.
+fn retry_or_skip() -> anyhow::Result<bool> {
+    let selection = Select::new()
+        .with_prompt("What would you like to do?")
+        .items(&["Retry", "Skip"])
+        .default(0)
+        .interact()?;
+    Ok(selection == 0)
+}
+
+// ── Channels ───────────────────────────────────────────────────────────────
+
+async fn setup_channels(plan: &mut SetupPlan) -> anyhow::Result<()> {
+    println!("\n  Channel Setup\n");
+    let choices: Vec<_> = channel_setup::available_channels()
+        .into_iter()
+        .filter(|c| WIZARD_CHANNELS.contains(&c.name))
+        .collect();
+    let items: Vec<String> = choices
+        .iter()
+        .map(|c| format!("{:<14} — {}", c.display_name, c.description))
+        .collect();
+    let picked = MultiSelect::new()
+        .with_prompt("Select channels to configure (space to toggle, enter to confirm)")
+        .items(&items)
+        .interact()?;
+    if picked.is_empty() {
+        println!("  No channels selected.");
+    }
+
+    for idx in picked {
+        if let Some(setup) = setup_channel_validated(choices[idx].name).await? {
+            plan.channels.retain(|c| c.name != setup.name);
+            plan.channels.push(setup);
+        }
+    }
+    Ok(())
+}
+
+/// Configure one channel and validate it with a live test message. Failures
+/// offer retry or skip; returns `None` if the channel was skipped.
+async fn setup_channel_validated(name: &str) -> anyhow::Result<Option<ChannelSetup>> {
+    'configure: loop {
+        let setup = match channel_setup::configure_channel(name).await {
+            Ok(setup) => setup,
+            Err(e) => {
+                println!("  Channel setup failed: {}", e);
+                if retry_or_skip()? {
+                    continue;
+                }
+                return Ok(None);
+            }
+        };
+
+        let mut recipient = Input::<String>::new().with_prompt(match setup.name {
+            "slack" => "Slack channel to send a test message to (e.g. #general)",
+            "telegram" => "Telegram chat ID to send a test message to",
+            _ => "Address to send a test email to",
+        });
+        if let Some(default) = setup.default_test_recipient() {
+            recipient = recipient.default(default);
+        }
+        let recipient = recipient.interact_text()?;
+
+        loop {
+            println!("\n  Sending a test message via {}...", setup.label);
+            match channel_setup::send_test_message(&setup, &recipient).await {
+                Ok(report) => {
+                    println!("  {}", report);
+                    return Ok(Some(setup));
+                }
+                Err(e) => {
+                    println!("  Validation failed: {}", e);
+                    let selection = Select::new()
+                        .with_prompt("What would you like to do?")
+                        .items(&[
+                            "Retry the test",
+                            "Re-enter credentials",
+                            "Save without validating",
+                            "Skip this channel",
+                        ])
+                        .default(0)
+                        .interact()?;
+                    match selection {
+                        0 => continue,
+                        1 => continue 'configure,
+                        2 => return Ok(Some(setup)),
+                        _ => return Ok(None),
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ── Voice ──────────────────────────────────────────────────────────────────
+
+async fn setup_voice(workspace: &Path) -> anyhow::Result<Option<VoiceConfig>> {
+    println!("\n  Voice Setup\n");
+    let mut voice = rustant_core::config::load_config(Some(workspace), None)
+        .ok()
+        .and_then(|c| c.voice)
+        .unwrap_or_default();
+
+    // Step 1: Microphone
+    loop {
+        println!("  Checking the microphone (recording for 1 second)...");
+        match check_microphone().await {
+            Ok(report) => {
+                println!("  {}", report);
+                break;
+            }
+            Err(e) => {
+                println!("  Microphone check failed: {}", e);
+                if !retry_or_skip()? {
+                    println!("  Continuing without a verified microphone.");
+                    break;
+                }
+            }
+        }
+    }
+
+    // Step 2: Activation mode
+    let modes = [
+        "Wake word (say a phrase like \"hey rustant\")",
+        "Push-to-talk (press Enter, then speak)",
+    ];
+    let selection = Select::new()
+        .with_prompt("How should voice mode start listening?")
+        .items(&modes)
+        .default(match voice.activation {
+            VoiceActivation::WakeWord => 0,
+            VoiceActivation::PushToTalk => 1,
+        })
+        .interact()?;
+    voice.activation = if selection == 0 {
+        VoiceActivation::WakeWord
+    } else {
+        VoiceActivation::PushToTalk
+    };
+    if voice.activation == VoiceActivation::WakeWord {
+        let wake_word: String = Input::new()
+            .with_prompt("Wake word")
+            .default(
+                voice
+                    .wake_words
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| "hey rustant".to_string()),
+            )
+            .interact_text()?;
+        voice.wake_words = vec![wake_word.trim().to_lowercase()];
+    }
+
+    // Step 3: TTS output
+    match resolve_openai_key() {
+        None => println!(
+            "  No OpenAI API key found (OPENAI_API_KEY or credential store); skipping the speech test."
+        ),
+        Some(api_key) => loop {
+            println!("  Playing a test phrase...");
+            match test_tts(&api_key, &voice).await {
+                Ok(()) => {
+                    println!("  Speech output works.");
+                    break;
+                }
+                Err(e) => {
+                    println!("  Speech test failed: {}", e);
+                    if !retry_or_skip()? {
+                        break;
+                    }
+                }
+            }
+        },
+    }
+
+    voice.enabled = true;
+    Ok(Some(voice))
+}
+
+/// Record a short clip to confirm a microphone is available.
+async fn check_microphone() -> anyhow::Result<String> {
+    let chunk = rustant_core::voice::record_audio_chunk(1.0, 16000).await?;
+    if chunk.is_empty() {
+        anyhow::bail!("no audio was captured");
+    }
+    Ok(format!(
+        "Microphone available ({:.1}s captured at {} Hz)",
+        chunk.duration_secs(),
+        chunk.sample_rate
+    ))
+}
+
+/// The OpenAI key used by the voice providers: environment first, then the
+/// credential store.
+fn resolve_openai_key() -> Option<String> {
+    std::env::var("OPENAI_API_KEY")
+        .ok()
+        .or_else(|| configured_store().get_key("openai").ok())
+        .filter(|k| !k.trim().is_empty())
+}
+
+async fn test_tts(api_key: &str, voice: &VoiceConfig) -> anyhow::Result<()> {
+    use rustant_core::voice::{OpenAiTtsProvider, SynthesisRequest, TtsProvider};
+
+    let request = SynthesisRequest::new("Voice setup is working.")
+        .with_voice(&voice.tts_voice)
+        .with_speed(voice.tts_speed);
+    let result = OpenAiTtsProvider::new(api_key).synthesize(&request).await?;
+    rustant_core::voice::play_audio(&result.audio).await?;
+    Ok(())
+}
+
+fn print_summary(sections: &[SetupSection], provider_done: bool, plan: &SetupPlan) {
+    println!("\n  Setup Summary\n");
+    for section in sections {
+        match section {
+            SetupSection::Provider => println!(
+                "    Provider:  {}",
+                if provider_done {
+                    "configured"
+                } else {
+                    "skipped"
+                }
+            ),
+            SetupSection::Channels if plan.channels.is_empty() => {
+                println!("    Channels:  none")
+            }
+            SetupSection::Channels => {
+                let labels: Vec<&str> = plan.channels.iter().map(|c| c.label).collect();
+                println!("    Channels:  {}", labels.join(", "));
+            }
+            SetupSection::Voice => match &plan.voice {
+                Some(voice) => {
+                    let mode = match voice.activation {
+                        VoiceActivation::WakeWord => {
+                            format!("wake word \"{}\"", voice.wake_words.join("\", \""))
+                        }
+                        VoiceActivation::PushToTalk => "push-to-talk".to_string(),
+                    };
+                    println!("    Voice:     enabled ({})", mode);
+                }
+                None => println!("    Voice:     not configured"),
+            },
+        }
+    }
+    if !plan.channels.is_empty() {
+        println!("\n  Channel secrets are stored in the OS credential store and referenced");
+        println!("  from config as keychain: entries.");
+    }
+}
+
+/// Run the interactive provider setup.
 ///
 /// Guides the user through provider selection, auth method choice, model selection,
 /// and configuration saving. Supports both API key and OAuth browser-based login.
-pub async fn run_setup(workspace: &Path) -> anyhow::Result<()> {
+pub async fn run_provider_setup(workspace: &Path) -> anyhow::Result<()> {
     println!("\n  Rustant Provider Setup\n");
 
     // Step 1: Provider selection
@@ -174,7 +582,7 @@ pub async fn run_setup(workspace: &Path) -> anyhow::Result<()> {
         false
     };
 
-    let cred_store = KeyringCredentialStore::new();
+    let cred_store = configured_store();
     let auth_method: &str;
     let api_key: String;
 
@@ -376,7 +784,7 @@ pub async fn run_setup(workspace: &Path) -> anyhow::Result<()> {
 
 /// Fallback: run the API key setup flow when OAuth fails.
 async fn run_setup_api_key(workspace: &Path, provider: &ProviderChoice) -> anyhow::Result<()> {
-    let cred_store = KeyringCredentialStore::new();
+    let cred_store = configured_store();
 
     // Check for existing key before prompting
     if let Ok(existing_key) = cred_store.get_key(&provider.name)
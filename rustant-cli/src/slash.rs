@@ -2,6 +2,10 @@
 //!
 //! Provides structured metadata for all `/command` slash commands,
 //! enabling categorized help, alias resolution, and tab completion.
+//! User-defined commands from config are held alongside the built-ins and
+//! listed separately in `/help`.
+
+use rustant_core::{CustomCommand, CustomCommandError, CustomCommandSet};
 
 /// Categories for grouping commands in `/help` output.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -66,6 +70,7 @@ pub struct CommandInfo {
 /// Registry holding all slash commands with their metadata.
 pub struct CommandRegistry {
     commands: Vec<CommandInfo>,
+    custom: CustomCommandSet,
 }
 
 #[allow(dead_code)]
@@ -74,6 +79,7 @@ impl CommandRegistry {
     pub fn new() -> Self {
         Self {
             commands: Vec::new(),
+            custom: CustomCommandSet::default(),
         }
     }
 
@@ -89,6 +95,33 @@ impl CommandRegistry {
         self.commands.push(info);
     }
 
+    /// Install user-defined commands, rejecting any that shadow a built-in name or alias.
+    pub fn register_custom(&mut self, custom: CustomCommandSet) -> Result<(), CustomCommandError> {
+        for cmd in custom.iter() {
+            if let Some(builtin) = self.lookup(&cmd.name) {
+                return Err(CustomCommandError::BuiltinCollision {
+                    name: cmd.name.clone(),
+                    builtin: builtin.name.to_string(),
+                });
+            }
+        }
+        self.custom = custom;
+        Ok(())
+    }
+
+    /// Look up a user-defined command by name.
+    pub fn custom_command(&self, name: &str) -> Option<&CustomCommand> {
+        self.custom.get(name)
+    }
+
+    /// One-line description of a built-in or custom command, for completion hints.
+    pub fn describe(&self, name: &str) -> Option<&str> {
+        self.lookup(name)
+            .map(|cmd| cmd.description)
+            .or_else(|| self.custom.get(name).map(|cmd| cmd.description.as_str()))
+            .filter(|d| !d.is_empty())
+    }
+
     /// Register all built-in commands.
     pub fn register_defaults(&mut self) {
         // Session commands
@@ -128,14 +161,50 @@ impl CommandRegistry {
             tui_only: false,
             detailed_help: None,
         });
+        self.register(CommandInfo {
+            name: "/continue",
+            aliases: &[],
+            description: "Continue an interrupted plan task",
+            usage: "/continue",
+            category: CommandCategory::Session,
+            tui_only: false,
+            detailed_help: Some("Pick up the most recent plan task in this workspace that did not finish, e.g. after a crash or Ctrl+C.\n\nCompleted steps are summarized instead of re-run, and files the task changed are checked for edits made outside it. A step that was interrupted part-way is settled first: its verification command is re-run if it has one, otherwise its file changes are rolled back. The task then continues from the first incomplete step and its cost is reported as a continuation of the original run."),
+        });
         self.register(CommandInfo {
             name: "/sessions",
             aliases: &[],
             description: "List, search, tag, or filter saved sessions",
-            usage: "/sessions [search <q> | tag <name> <tag> | filter <tag>]",
+            usage: "/sessions [search <q> | tag <name> <tag> | filter <tag> | delete <name>]",
+            category: CommandCategory::Session,
+            tui_only: false,
+            detailed_help: Some("Manage saved sessions.\n\nSubcommands:\n  /sessions              - List recent sessions\n  /sessions search <q>   - Search sessions by name, goal, or summary\n  /sessions tag <n> <t>  - Add a tag to a session\n  /sessions filter <tag> - List sessions with a specific tag\n  /sessions delete <n>   - Delete a session, e.g. a discarded branch\n\nBranches are listed under the session they were forked from.\n\nExamples:\n  /sessions search auth  - Find sessions related to auth\n  /sessions tag my-proj bugfix - Tag session 'my-proj' with 'bugfix'\n  /sessions filter refactor    - List all refactoring sessions"),
+        });
+        self.register(CommandInfo {
+            name: "/fork",
+            aliases: &[],
+            description: "Branch the conversation at an earlier turn",
+            usage: "/fork [<turn> [session]]",
+            category: CommandCategory::Session,
+            tui_only: false,
+            detailed_help: Some("Start a new session branched from an earlier turn, keeping the first <turn> user turns and dropping everything after them, including facts learned later. Without a session name the current conversation is saved first and forked; the branch then becomes the current conversation. Artifact files are shared with the parent, not copied.\n\nExamples:\n  /fork               - List the turns of the current conversation\n  /fork 3             - Continue from just after turn 3\n  /fork 3 auth-fix    - Branch the saved session 'auth-fix' at turn 3"),
+        });
+        self.register(CommandInfo {
+            name: "/compare",
+            aliases: &[],
+            description: "Compare the artifacts of two session branches",
+            usage: "/compare <branch> [other-branch]",
             category: CommandCategory::Session,
             tui_only: false,
-            detailed_help: Some("Manage saved sessions.\n\nSubcommands:\n  /sessions              - List recent sessions\n  /sessions search <q>   - Search sessions by name, goal, or summary\n  /sessions tag <n> <t>  - Add a tag to a session\n  /sessions filter <tag> - List sessions with a specific tag\n\nExamples:\n  /sessions search auth  - Find sessions related to auth\n  /sessions tag my-proj bugfix - Tag session 'my-proj' with 'bugfix'\n  /sessions filter refactor    - List all refactoring sessions"),
+            detailed_help: Some("Show which files each branch created or changed since they diverged, and whether their contents differ, using the artifact manifest saved with each session. Compares against the parent session unless another branch is given.\n\nExamples:\n  /compare auth-fix-fork-3          - Branch vs. the session it came from\n  /compare retry-a retry-b          - Two branches of the same session"),
+        });
+        self.register(CommandInfo {
+            name: "/workspace",
+            aliases: &[],
+            description: "Show named workspaces or switch to one",
+            usage: "/workspace [name]",
+            category: CommandCategory::Session,
+            tui_only: false,
+            detailed_help: Some("Switch between workspaces registered with `rustant workspace add`.\n\nThe current session and scheduler state are saved to the old workspace. Memory, facts, tools and scheduled jobs are then rebuilt for the new workspace and its latest session is resumed; nothing from the old workspace carries over.\n\nExamples:\n  /workspace       - List named workspaces (* marks the current one)\n  /workspace acme  - Switch to the workspace named 'acme'"),
         });
 
         // Agent commands
@@ -193,24 +262,60 @@ impl CommandRegistry {
             tui_only: false,
             detailed_help: None,
         });
+        self.register(CommandInfo {
+            name: "/learned",
+            aliases: &[],
+            description: "Show facts learned this session and review queued ones",
+            usage: "/learned [review|accept <n>|reject <n>]",
+            category: CommandCategory::Agent,
+            tui_only: false,
+            detailed_help: Some("When a session closes, and before older messages are compressed, Rustant extracts durable facts from the conversation: project conventions, key file locations, decisions and your preferences. Confident facts are stored in .rustant/knowledge/learned.json and offered to later sessions in this workspace; less confident ones wait for review. Configure thresholds and the per-session cap under [knowledge].\n\nExamples:\n  /learned            - Show what this session learned\n  /learned review     - List facts waiting for review\n  /learned accept 2   - Store queued fact #2\n  /learned reject 1   - Discard queued fact #1"),
+        });
         self.register(CommandInfo {
             name: "/pin",
             aliases: &[],
-            description: "Pin message #n (survives compression) or list pinned",
-            usage: "/pin [n]",
+            description: "Pin message #n, or a file or fact into every prompt",
+            usage: "/pin [n|path[:start-end]|fact]",
             category: CommandCategory::Agent,
             tui_only: false,
-            detailed_help: None,
+            detailed_help: Some("Pin a message by number so it survives compression, or pin a file or fact into the system prompt of every request. Pinned files are re-read from disk before each request and cut to [memory] pin_token_budget tokens; use path:start-end for part of a large file. Pins are saved with the session and restored on resume.\n\nExamples:\n  /pin 3                      - Keep message #3 through compression\n  /pin docs/api.md            - Pin a file\n  /pin src/lib.rs:1-80        - Pin lines 1-80 of a file\n  /pin The API is frozen      - Pin a fact"),
         });
         self.register(CommandInfo {
             name: "/unpin",
             aliases: &[],
-            description: "Unpin message #n",
-            usage: "/unpin <n>",
+            description: "Unpin message #n, or a pinned file or fact",
+            usage: "/unpin <n|path|fact>",
+            category: CommandCategory::Agent,
+            tui_only: false,
+            detailed_help: None,
+        });
+        self.register(CommandInfo {
+            name: "/pins",
+            aliases: &[],
+            description: "List pinned files and facts with their token cost",
+            usage: "/pins",
             category: CommandCategory::Agent,
             tui_only: false,
             detailed_help: None,
         });
+        self.register(CommandInfo {
+            name: "/attach",
+            aliases: &[],
+            description: "Attach an image file or the clipboard image to your next message",
+            usage: "/attach [path|clipboard|clear]",
+            category: CommandCategory::Agent,
+            tui_only: false,
+            detailed_help: Some("Attach a PNG, JPEG, GIF or WebP image to your next message. Images are checked, downscaled to 1568 px on the long edge and 5 MB if needed, and stored as attachment handles; only the handle is saved with the session. Vision models receive the image itself. For a model without vision, the [llm] vision_fallback model describes the image and the labeled description is sent instead. Dragging an image file onto the terminal attaches it too.\n\nExamples:\n  /attach design/mockup.png   - Attach a workspace file\n  /attach clipboard           - Attach the image on the clipboard (macOS)\n  /attach                     - List attached images\n  /attach clear               - Drop attached images"),
+        });
+        self.register(CommandInfo {
+            name: "/clip",
+            aliases: &[],
+            description: "Attach an entry from the clipboard history to your next message",
+            usage: "/clip [id|search <text>|purge]",
+            category: CommandCategory::Agent,
+            tui_only: false,
+            detailed_help: Some("Pick something you copied earlier and attach it to your next message. Text, URLs and file paths are attached as a labeled block; images as attachment handles. The history is recorded only while `rustant ui` runs with [clipboard] history = true, stays in .rustant/clipboard/ encrypted with the session key, and never leaves this machine.\n\nExamples:\n  /clip                 - List recent entries; press Enter to attach the newest\n  /clip 42              - Attach entry #42\n  /clip search invoice  - Pick among entries containing 'invoice'\n  /clip purge           - Delete the clipboard history"),
+        });
 
         // Safety commands
         self.register(CommandInfo {
@@ -235,10 +340,10 @@ impl CommandRegistry {
             name: "/audit",
             aliases: &[],
             description: "Show, query, export, or verify audit trail",
-            usage: "/audit [show [n] | verify | export [fmt] | query <tool>]",
+            usage: "/audit [show [n] | verify | export [fmt] [opts] | query <tool>]",
             category: CommandCategory::Safety,
             tui_only: false,
-            detailed_help: None,
+            detailed_help: Some("Export formats: json, jsonl, csv, text, ocsf, cef.\n\nOCSF and CEF exports take options:\n  since=<time>   RFC 3339, YYYY-MM-DD, or an age like 12h or 7d\n  until=<time>   Same formats as since\n  type=<tags>    Comma-separated event types, e.g. tool_executed,tool_denied\n  out=<path>     Write events to a file and metadata to <path>.meta.json\n\nExamples:\n  /audit export ocsf since=24h out=audit.ocsf.jsonl\n  /audit export cef type=tool_denied"),
         });
 
         // Development commands
@@ -322,10 +427,10 @@ impl CommandRegistry {
             name: "/trust",
             aliases: &[],
             description: "Show safety trust dashboard with per-tool approval stats",
-            usage: "/trust",
+            usage: "/trust [reset <tool|workspace [path]|all>]",
             category: CommandCategory::Safety,
             tui_only: false,
-            detailed_help: Some("Display a trust calibration dashboard showing:\n  - Current approval mode with plain-English explanation\n  - Per-tool approval/denial statistics from the audit log\n  - Suggestions for adjusting trust based on your usage patterns\n\nThe dashboard helps you understand why you are being prompted and\nmake informed decisions about adjusting your approval mode."),
+            detailed_help: Some("Display a trust calibration dashboard showing:\n  - Current approval mode with plain-English explanation\n  - Per-tool approval/denial statistics from the audit log\n  - Suggestions for adjusting trust based on your usage patterns\n  - Adaptive trust scores per tool and workspace, with the events\n    that moved them most (when [safety.adaptive_trust] is enabled)\n\n/trust reset <tool> forgets a tool's trust in every workspace;\n/trust reset workspace [path] forgets all trust in a workspace\n(default: this one); /trust reset all clears everything.\n\nThe dashboard helps you understand why you are being prompted and\nmake informed decisions about adjusting your approval mode."),
         });
 
         // Keys command
@@ -647,12 +752,14 @@ impl CommandRegistry {
             name: "/skill",
             aliases: &[],
             description: "Manage skills (SKILL.md files)",
-            usage: "/skill list|info|validate <path>",
+            usage: "/skill list|install <source>|update [name]|info|validate <path>",
             category: CommandCategory::System,
             tui_only: false,
             detailed_help: Some(
                 "Manage SKILL.md skill definitions.\n\n\
-                 Usage:\n  /skill list              — List loaded skills\n  \
+                 Usage:\n  /skill list              — List skills with origin and updates\n  \
+                 /skill install <source>  — Install from a path, git or HTTPS URL\n  \
+                 /skill update [name]     — Re-fetch installed skills and show diffs\n  \
                  /skill info <path>       — Show skill details\n  \
                  /skill validate <path>   — Validate a skill file\n\n\
                  Equivalent to: rustant skill <subcommand>",
@@ -676,13 +783,15 @@ impl CommandRegistry {
             name: "/update",
             aliases: &[],
             description: "Check for or install updates",
-            usage: "/update check|install",
+            usage: "/update check|install|rollback",
             category: CommandCategory::System,
             tui_only: false,
             detailed_help: Some(
                 "Check for and install Rustant updates.\n\n\
                  Usage:\n  /update check    — Check for available updates\n  \
-                 /update install  — Download and install the latest version\n\n\
+                 /update install  — Download, verify, and install the latest version\n  \
+                 /update rollback — Restore the binary replaced by the last install\n\n\
+                 The release channel (stable, beta, nightly) comes from [update] in config.\n\n\
                  Equivalent to: rustant update <subcommand>",
             ),
         });
@@ -758,6 +867,45 @@ impl CommandRegistry {
             ),
         });
 
+        // ── Personas ──
+        self.register(CommandInfo {
+            name: "/persona",
+            aliases: &[],
+            description: "Switch agent persona (tone, tools, approval)",
+            usage: "/persona [name|list|off]",
+            category: CommandCategory::Agent,
+            tui_only: false,
+            detailed_help: Some(
+                "Switch the agent persona mid-session.\n\n\
+                 A persona adds a system prompt fragment, limits which tools the\n\
+                 model can see, and may override the approval mode and model.\n\n\
+                 Usage:\n  /persona list   — List available personas\n  \
+                 /persona <name> — Activate a persona\n  \
+                 /persona off    — Return to the default agent\n  \
+                 /persona        — Show the active persona\n\n\
+                 Built-in: ops-sre, writing-assistant. Define more under\n\
+                 [[personas]] in .rustant/config.toml, or start with --persona <name>.",
+            ),
+        });
+
+        // ── Artifacts ──
+        self.register(CommandInfo {
+            name: "/artifacts",
+            aliases: &["/art"],
+            description: "List files, reports, and canvas items produced this session",
+            usage: "/artifacts [list | open <n> | reveal <n>]",
+            category: CommandCategory::Session,
+            tui_only: false,
+            detailed_help: Some(
+                "Browse the durable outputs tools produced this session.\n\n\
+                 Usage:\n  /artifacts          — List artifacts with type, source tool, and time\n  \
+                 /artifacts open <n>   — Open a file artifact with the system handler\n  \
+                 /artifacts reveal <n> — Show a file artifact in the file manager\n\n\
+                 Files deleted since they were produced are marked [stale].\n\
+                 Artifacts are saved with the session and restored on /resume.",
+            ),
+        });
+
         // ── ArXiv Research ──
         self.register(CommandInfo {
             name: "/arxiv",
@@ -1066,6 +1214,13 @@ impl CommandRegistry {
             }
         }
 
+        if !self.custom.is_empty() {
+            output.push_str("\n  Custom commands:\n");
+            for cmd in self.custom.iter() {
+                output.push_str(&format!("    {:<24} {}\n", cmd.usage(), cmd.description));
+            }
+        }
+
         output.push_str("\nInput:\n  Type your task or question and press Enter.\n");
         output
     }
@@ -1083,6 +1238,11 @@ impl CommandRegistry {
                 }
             }
         }
+        for cmd in self.custom.iter() {
+            if cmd.name.starts_with(prefix) {
+                results.push(cmd.name.as_str());
+            }
+        }
         results.sort();
         results
     }
@@ -1133,6 +1293,16 @@ impl CommandRegistry {
             return Some(output);
         }
 
+        if let Some(cmd) = self.custom.get(&topic_with_slash) {
+            let mut output = format!("{} - {} (custom)\n", cmd.name, cmd.description);
+            output.push_str(&format!("Usage: {}\n", cmd.usage()));
+            if let Some(mode) = cmd.requires_approval_mode {
+                output.push_str(&format!("Requires approval mode: {} or stricter\n", mode));
+            }
+            output.push_str(&format!("\nTemplate:\n{}\n", cmd.template()));
+            return Some(output);
+        }
+
         // Try category match
         for cat in CommandCategory::all() {
             if cat.label().to_lowercase() == topic_lower {
@@ -1175,6 +1345,12 @@ impl CommandRegistry {
                 }
             }
         }
+        for cmd in self.custom.iter() {
+            let dist = edit_distance(input, &cmd.name);
+            if dist <= 3 && (best.is_none() || dist < best.unwrap().1) {
+                best = Some((cmd.name.as_str(), dist));
+            }
+        }
 
         best.map(|(name, _)| name)
     }
@@ -1457,4 +1633,49 @@ mod tests {
             "Help text should contain (TUI) markers for TUI-only commands"
         );
     }
+
+    fn custom_set(name: &str) -> CustomCommandSet {
+        let config = rustant_core::CustomCommandConfig {
+            name: name.into(),
+            description: "Deploy the current branch".into(),
+            template: "Deploy the current branch to {env}".into(),
+            args: vec!["env".into()],
+            defaults: Default::default(),
+            requires_approval_mode: None,
+        };
+        CustomCommandSet::from_configs(&[config], "config.toml").unwrap()
+    }
+
+    #[test]
+    fn test_custom_commands_listed_and_completed() {
+        let mut registry = CommandRegistry::with_defaults();
+        registry.register_custom(custom_set("deploy")).unwrap();
+
+        assert!(registry.custom_command("/deploy").is_some());
+        assert!(registry.lookup("/deploy").is_none());
+        assert_eq!(registry.completions("/depl"), vec!["/deploy"]);
+        assert_eq!(
+            registry.describe("/deploy"),
+            Some("Deploy the current branch")
+        );
+        assert_eq!(registry.suggest("/deplyo"), Some("/deploy"));
+
+        let help = registry.help_text();
+        let custom_section = help.find("Custom commands:").unwrap();
+        assert!(help[custom_section..].contains("/deploy <env>"));
+        assert!(registry.help_for("deploy").unwrap().contains("(custom)"));
+    }
+
+    #[test]
+    fn test_custom_command_builtin_collision_rejected() {
+        let mut registry = CommandRegistry::with_defaults();
+        let err = registry.register_custom(custom_set("help")).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("collides with built-in command '/help'")
+        );
+        // Aliases are reserved too.
+        assert!(registry.register_custom(custom_set("q")).is_err());
+        assert!(registry.custom_command("/q").is_none());
+    }
 }
@@ -22,7 +22,10 @@ use crate::tui::widgets::task_board::{TaskBoard, render_task_board};
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout};
-use rustant_core::audit::{Analytics, AuditExporter, AuditQuery, AuditStore, ExecutionTrace};
+use rustant_core::audit::{
+    Analytics, AuditExporter, AuditQuery, AuditStore, ExecutionTrace, SiemFormat,
+};
+use rustant_core::pins::PinTarget;
 use rustant_core::replay::ReplaySession;
 use rustant_core::types::{AgentStatus, Role};
 use rustant_core::{
@@ -95,7 +98,13 @@ pub struct App {
 
     // Voice & meeting toggle state
     toggle_state: std::sync::Arc<rustant_core::ToggleState>,
+    /// Requests from the voice command session.
+    voice_turns: Option<mpsc::Receiver<rustant_core::VoiceTurn>>,
+    /// The spoken request being processed and the reply so far.
+    voice_turn: Option<(rustant_core::VoiceTurn, String)>,
     config_snapshot: rustant_core::AgentConfig,
+    /// Built-in and user-defined slash commands.
+    command_registry: crate::slash::CommandRegistry,
 
     // App state
     pub should_quit: bool,
@@ -109,7 +118,9 @@ impl App {
         let theme = Theme::from_name(&config.ui.theme);
         let vim_mode = config.ui.vim_mode;
 
+        let toggle_state = rustant_core::ToggleState::new();
         let (callback, callback_rx) = TuiCallback::new();
+        let callback = callback.with_toggle_state(toggle_state.clone());
         let provider = match rustant_core::create_provider(&config.llm) {
             Ok(p) => p,
             Err(e) => {
@@ -117,6 +128,11 @@ impl App {
                 Arc::new(MockLlmProvider::new())
             }
         };
+        rustant_tools::reader_capture::set_topic_tagger(Arc::clone(&provider));
+        rustant_tools::lsp::pre_write::install_pre_write_check(
+            &config.tools.pre_write_diagnostics,
+            &workspace,
+        );
         let callback_arc = Arc::new(callback);
         let mut agent = Agent::new(provider, config.clone(), callback_arc);
 
@@ -125,10 +141,13 @@ impl App {
         let mut registry = ToolRegistry::new();
         register_builtin_tools_with_progress(&mut registry, workspace.clone(), Some(progress_tx));
         register_agent_tools(&mut agent, &registry, &workspace);
+        if let Some(trust_path) = rustant_core::trust::default_trust_path() {
+            agent.load_trust_store(&trust_path);
+        }
 
         let header = HeaderData {
             model: config.llm.model.clone(),
-            approval_mode: config.safety.approval_mode.to_string(),
+            approval_mode: agent.safety().approval_mode().to_string(),
             tokens_used: 0,
             context_window: config.llm.context_window,
             cost_usd: 0.0,
@@ -142,6 +161,19 @@ impl App {
             ..Default::default()
         };
 
+        let mut command_registry = crate::slash::CommandRegistry::with_defaults();
+        let mut command_palette = CommandPalette::new();
+        let custom_commands_error =
+            match rustant_core::CustomCommandSet::load(&config.commands, &workspace) {
+                Ok(custom) => {
+                    for cmd in custom.iter() {
+                        command_palette.add_command(&cmd.name, &cmd.description);
+                    }
+                    command_registry.register_custom(custom).err()
+                }
+                Err(e) => Some(e),
+            };
+
         let mut app = Self {
             conversation: ConversationState::new(),
             input: InputWidget::new(&theme),
@@ -156,7 +188,7 @@ impl App {
             show_sidebar: true,
             highlighter: SyntaxHighlighter::new(),
             autocomplete: AutocompleteState::new(workspace.clone()),
-            command_palette: CommandPalette::new(),
+            command_palette,
             diff_view: DiffView::new(),
             checkpoint_manager: CheckpointManager::new(workspace.clone()),
             callback_rx,
@@ -175,10 +207,14 @@ impl App {
             plan_panel: PlanPanel::new(),
             status_bar_data: StatusBarData {
                 context_window: config.llm.context_window,
+                persona: config.persona.clone(),
                 ..Default::default()
             },
-            toggle_state: rustant_core::ToggleState::new(),
+            toggle_state,
+            voice_turns: None,
+            voice_turn: None,
             config_snapshot: config.clone(),
+            command_registry,
             should_quit: false,
             is_processing: false,
             vim_mode,
@@ -190,6 +226,9 @@ impl App {
         app.try_recover_session();
         // Show first-run onboarding if applicable
         app.show_onboarding_if_needed();
+        if let Some(e) = custom_commands_error {
+            app.push_system_msg(&format!("Custom commands not loaded: {}", e));
+        }
 
         app
     }
@@ -268,6 +307,13 @@ impl App {
                         self.progress.apply_progress(&update);
                     }
                 }
+                // Spoken requests from the voice session
+                turn = next_voice_turn(&mut self.voice_turns) => {
+                    match turn {
+                        Some(turn) => self.submit_voice_turn(turn),
+                        None => self.voice_turns = None,
+                    }
+                }
                 // Tick
                 _ = tokio::time::sleep(tick_rate) => {
                     // Tick for spinners/animation updates
@@ -276,6 +322,7 @@ impl App {
             }
 
             if self.should_quit {
+                self.agent.distill_session().await;
                 // Auto-save session and persist history before exit
                 self.auto_save_session();
                 self.save_history();
@@ -981,11 +1028,26 @@ impl App {
             return;
         }
 
-        // Handle slash commands locally
-        if text.starts_with('/') {
-            self.handle_command(&text);
-            return;
-        }
+        // Expand user-defined commands into their prompt; handle the rest locally
+        let text = if text.starts_with('/') {
+            let name = text.split_whitespace().next().unwrap_or(&text);
+            let Some(custom) = self.command_registry.custom_command(name) else {
+                self.handle_command(&text);
+                return;
+            };
+            let expanded = custom
+                .check_approval_mode(self.agent.safety().approval_mode())
+                .and_then(|()| custom.expand(text[name.len()..].trim()));
+            match expanded {
+                Ok(prompt) => prompt,
+                Err(e) => {
+                    self.push_system_msg(&e.to_string());
+                    return;
+                }
+            }
+        } else {
+            text
+        };
 
         // Add user message to conversation
         self.conversation.push_message(DisplayMessage {
@@ -1026,9 +1088,17 @@ impl App {
                     TokenAlert::Overflow => "[OVERFLOW] ",
                     TokenAlert::Normal => "",
                 };
+                let mut text = format!("{}{}", alert_prefix, display.format_display());
+                if let Some(cache) = self.agent.brain().response_cache() {
+                    text.push_str(&format!(
+                        "\nCache (task): {}\nCache (overall): {}",
+                        self.agent.brain().cache_task_stats(),
+                        cache.stats()
+                    ));
+                }
                 self.conversation.push_message(DisplayMessage {
                     role: Role::System,
-                    text: format!("{}{}", alert_prefix, display.format_display()),
+                    text,
                     tool_name: None,
                     is_error: matches!(display.alert, TokenAlert::Critical | TokenAlert::Overflow),
                     timestamp: chrono::Utc::now().format("%H:%M:%S").to_string(),
@@ -1050,10 +1120,9 @@ impl App {
                 });
             }
             "/help" | "/?" => {
-                let registry = crate::slash::CommandRegistry::with_defaults();
                 self.conversation.push_message(DisplayMessage {
                     role: Role::System,
-                    text: registry.help_text(),
+                    text: self.command_registry.help_text(),
                     tool_name: None,
                     is_error: false,
                     timestamp: chrono::Utc::now().format("%H:%M:%S").to_string(),
@@ -1157,6 +1226,25 @@ impl App {
                                 "jsonl" => AuditExporter::to_jsonl(&refs)
                                     .unwrap_or_else(|e| format!("Export error: {}", e)),
                                 "csv" => AuditExporter::to_csv(&refs),
+                                "ocsf" | "cef" => {
+                                    let siem =
+                                        SiemFormat::parse(format).unwrap_or(SiemFormat::Ocsf);
+                                    let mut buf = Vec::new();
+                                    let query = AuditQuery::new();
+                                    match AuditExporter::write_siem(
+                                        &self.audit_store,
+                                        &query,
+                                        siem,
+                                        &mut buf,
+                                    ) {
+                                        Ok(meta) => format!(
+                                            "{}(chain: {})",
+                                            String::from_utf8_lossy(&buf),
+                                            meta.chain
+                                        ),
+                                        Err(e) => format!("Export error: {}", e),
+                                    }
+                                }
                                 _ => AuditExporter::to_text(&refs),
                             };
                             self.push_system_msg(&format!(
@@ -1191,7 +1279,8 @@ impl App {
                     }
                     _ => {
                         self.push_system_msg(
-                            "Usage: /audit [show [n] | verify | export [fmt] | query <tool>]",
+                            "Usage: /audit [show [n] | verify | export [fmt] | query <tool>]\n\
+                             Export formats: json, jsonl, csv, text, ocsf, cef",
                         );
                     }
                 }
@@ -1526,9 +1615,34 @@ impl App {
                     }
                 }
 
+                let trust = safety.adaptive_trust();
+                if trust.enabled {
+                    let report = trust.ledger.report(
+                        &trust.policy,
+                        &rustant_core::trust::TrustScope::All,
+                        chrono::Utc::now(),
+                    );
+                    text.push('\n');
+                    for line in report.lines() {
+                        text.push_str(&format!("\n  {}", line));
+                    }
+                    text.push_str("\n  Reset: /trust reset <tool|workspace [path]|all>\n");
+                }
+
                 text.push_str("\n  Change mode: /permissions <safe|cautious|paranoid|yolo>");
                 self.push_system_msg(&text);
             }
+            cmd if cmd.starts_with("/trust reset") => {
+                let target = cmd.strip_prefix("/trust reset").unwrap_or("");
+                let workspace = self.agent.safety().adaptive_trust().workspace().to_string();
+                match rustant_core::trust::TrustScope::parse(target, &workspace) {
+                    Some(scope) => {
+                        let removed = self.agent.safety_mut().reset_trust(&scope);
+                        self.push_system_msg(&format!("Reset {} trust score(s).", removed));
+                    }
+                    None => self.push_system_msg("Usage: /trust reset <tool|workspace [path]|all>"),
+                }
+            }
             "/doctor" => {
                 let config = self.agent.config();
                 let tools = self.agent.tool_definitions(None);
@@ -1590,7 +1704,7 @@ impl App {
                 }
             }
             // ── Missing commands: ported from REPL ──────────────────────
-            "/sessions" => match rustant_core::SessionManager::new(&self.workspace) {
+            "/sessions" => match crate::commands::open_sessions(&self.workspace) {
                 Ok(mgr) => {
                     let sessions = mgr.list_sessions(10);
                     if sessions.is_empty() {
@@ -1638,7 +1752,7 @@ impl App {
                             self.load_session(name);
                         }
                     }
-                    "list" => match rustant_core::SessionManager::new(&self.workspace) {
+                    "list" => match crate::commands::open_sessions(&self.workspace) {
                         Ok(mgr) => {
                             let sessions = mgr.list_sessions(10);
                             if sessions.is_empty() {
@@ -1665,7 +1779,7 @@ impl App {
             }
             other if other.starts_with("/resume") => {
                 let query = other.strip_prefix("/resume").unwrap_or("").trim();
-                match rustant_core::SessionManager::new(&self.workspace) {
+                match crate::commands::open_sessions(&self.workspace) {
                     Ok(mut mgr) => {
                         let result = if query.is_empty() {
                             mgr.resume_latest()
@@ -1675,6 +1789,9 @@ impl App {
                         match result {
                             Ok((memory, continuation)) => {
                                 *self.agent.memory_mut() = memory;
+                                self.agent.set_artifacts(mgr.active_artifacts());
+                                self.agent.set_task_runs(mgr.active_task_runs());
+                                self.agent.set_recording(mgr.active_recording());
                                 self.push_system_msg(&format!("Session resumed. {}", continuation));
                             }
                             Err(e) => {
@@ -1705,10 +1822,35 @@ impl App {
                 );
                 self.push_system_msg(&text);
             }
+            "/learned" => {
+                let learned = self.agent.learned();
+                let mut text = String::new();
+                for fact in &learned.stored {
+                    text.push_str(&format!("  + [{}] {}\n", fact.category, fact.content));
+                }
+                for fact in &learned.queued {
+                    text.push_str(&format!(
+                        "  ? [{}] {} ({:.2})\n",
+                        fact.category, fact.content, fact.confidence
+                    ));
+                }
+                let text = if text.is_empty() {
+                    "Nothing learned yet. Facts are extracted when the session closes and before context compression.".to_string()
+                } else {
+                    format!(
+                        "Learned this session (? = waiting for review):\n{}",
+                        text.trim_end()
+                    )
+                };
+                self.push_system_msg(&text);
+            }
             "/context" => {
                 let context_window = self.agent.brain().context_window();
                 let mem = self.agent.memory();
-                let ctx = mem.context_breakdown(context_window);
+                let mut ctx =
+                    mem.context_breakdown_with(context_window, self.agent.brain().token_counter());
+                let pinned = self.agent.pinned_context();
+                ctx.add_pinned(pinned.total_tokens());
                 let mut text = format!(
                     "Context Window Breakdown:\n  Window size: {} tokens\n",
                     ctx.context_window
@@ -1723,6 +1865,20 @@ impl App {
                 if ctx.pinned_count > 0 {
                     text.push_str(&format!("  Pinned: {} messages\n", ctx.pinned_count));
                 }
+                if !pinned.pins.is_empty() {
+                    text.push_str(&format!(
+                        "  Pinned context: ~{} tokens (exempt from compression)\n",
+                        ctx.pinned_tokens
+                    ));
+                    for pin in &pinned.pins {
+                        text.push_str(&format!(
+                            "    {} - ~{} tokens{}\n",
+                            pin.label,
+                            pin.tokens,
+                            pin.status_note()
+                        ));
+                    }
+                }
                 text.push_str(&format!(
                     "  Total used: ~{} tokens ({:.0}%)\n  Remaining: ~{} tokens\n",
                     ctx.total_tokens,
@@ -1740,6 +1896,31 @@ impl App {
                 }
                 self.push_system_msg(&text);
             }
+            "/pins" => {
+                let pinned = self.agent.pinned_context();
+                if pinned.pins.is_empty() {
+                    self.push_system_msg(
+                        "Nothing pinned. Use /pin <path[:start-end]|fact> to keep it in every prompt.",
+                    );
+                } else {
+                    let mut text = format!("Pinned context ({}):\n", pinned.pins.len());
+                    for pin in &pinned.pins {
+                        let by = if pin.by_agent { " [agent]" } else { "" };
+                        text.push_str(&format!(
+                            "  {} - ~{} tokens{}{}\n",
+                            pin.label,
+                            pin.tokens,
+                            pin.status_note(),
+                            by
+                        ));
+                    }
+                    text.push_str(&format!(
+                        "  Total: ~{} tokens per prompt",
+                        pinned.total_tokens()
+                    ));
+                    self.push_system_msg(&text);
+                }
+            }
             other if other.starts_with("/pin") => {
                 let arg = other.strip_prefix("/pin").unwrap_or("").trim();
                 if arg.is_empty() {
@@ -1788,9 +1969,26 @@ impl App {
                                 ));
                             }
                         }
-                        Err(_) => {
-                            self.push_system_msg("Usage: /pin <message_number>");
-                        }
+                        Err(_) => match PinTarget::parse(arg, &self.workspace) {
+                            Ok(target) => {
+                                if !self.agent.memory_mut().pins.pin(target, false) {
+                                    self.push_system_msg(&format!("{} is already pinned.", arg));
+                                } else if let Some(pin) =
+                                    self.agent.pinned_context().pins.last().cloned()
+                                {
+                                    self.push_system_msg(&format!(
+                                        "Pinned {} (~{} tokens in every prompt){}.",
+                                        pin.label,
+                                        pin.tokens,
+                                        pin.status_note()
+                                    ));
+                                }
+                            }
+                            Err(e) => self.push_system_msg(&format!(
+                                "{}. Usage: /pin <message_number|path[:start-end]|fact>",
+                                e
+                            )),
+                        },
                     }
                 }
             }
@@ -1804,9 +2002,16 @@ impl App {
                             self.push_system_msg(&format!("Message #{} was not pinned.", n));
                         }
                     }
-                    Err(_) => {
-                        self.push_system_msg("Usage: /unpin <message_number>");
+                    Err(_) if arg.is_empty() => {
+                        self.push_system_msg("Usage: /unpin <message_number|path|fact>");
                     }
+                    Err(_) => match self.agent.memory_mut().pins.unpin(arg) {
+                        Some(pin) => self.push_system_msg(&format!("Unpinned {}.", pin.label())),
+                        None => self.push_system_msg(&format!(
+                            "Nothing pinned matches '{}'. See /pins.",
+                            arg
+                        )),
+                    },
                 }
             }
             "/safety" => {
@@ -2110,6 +2315,63 @@ impl App {
                     }
                 }
             }
+            cmd if cmd == "/artifacts"
+                || cmd.starts_with("/artifacts ")
+                || cmd == "/art"
+                || cmd.starts_with("/art ") =>
+            {
+                let rest = cmd.split_once(' ').map(|(_, r)| r.trim()).unwrap_or("");
+                let (sub, arg) = rest.split_once(' ').unwrap_or((rest, ""));
+                let msg = crate::repl::artifacts_command(&mut self.agent, sub, arg.trim());
+                self.push_system_msg(&msg);
+            }
+            cmd if cmd == "/persona" || cmd.starts_with("/persona ") => {
+                let sub = cmd.strip_prefix("/persona").unwrap_or("").trim();
+                match sub {
+                    "" => {
+                        let msg = match self.agent.active_persona() {
+                            Some(p) => format!("Active persona: {}", p.name),
+                            None => "No active persona. Use /persona list to see options.".into(),
+                        };
+                        self.push_system_msg(&msg);
+                    }
+                    "list" => {
+                        let registry = rustant_core::PersonaRegistry::from_config(
+                            &self.config_snapshot.personas,
+                        );
+                        let active = self.agent.active_persona().map(|p| p.name.clone());
+                        let mut lines = vec!["Personas:".to_string()];
+                        for p in registry.iter() {
+                            let marker = if active.as_deref() == Some(p.name.as_str()) {
+                                "*"
+                            } else {
+                                " "
+                            };
+                            lines.push(format!("  {} {:<20} {}", marker, p.name, p.description));
+                        }
+                        self.push_system_msg(&lines.join("\n"));
+                    }
+                    name => {
+                        let target = if name == "off" || name == "none" {
+                            None
+                        } else {
+                            Some(name)
+                        };
+                        let config = self.config_snapshot.clone();
+                        match crate::repl::switch_persona(&mut self.agent, &config, target) {
+                            Ok(msg) => {
+                                self.status_bar_data.persona =
+                                    self.agent.active_persona().map(|p| p.name.clone());
+                                self.header.model = self.agent.brain().model_name().to_string();
+                                self.header.approval_mode =
+                                    self.agent.safety().approval_mode().to_string();
+                                self.push_system_msg(&msg);
+                            }
+                            Err(e) => self.push_system_msg(&format!("[Error] {}", e)),
+                        }
+                    }
+                }
+            }
             cmd if cmd.starts_with("/meeting") || cmd.starts_with("/meet ") || cmd == "/meet" => {
                 let sub = cmd
                     .strip_prefix("/meeting")
@@ -2168,9 +2430,8 @@ impl App {
             }
             other => {
                 // Use registry for unknown command suggestions
-                let registry = crate::slash::CommandRegistry::with_defaults();
                 let cmd_name = other.split_whitespace().next().unwrap_or(other);
-                let msg = if let Some(suggestion) = registry.suggest(cmd_name) {
+                let msg = if let Some(suggestion) = self.command_registry.suggest(cmd_name) {
                     format!(
                         "Unknown command: {}. Did you mean {}?",
                         cmd_name, suggestion
@@ -2211,13 +2472,11 @@ impl App {
             self.push_system_msg("Voice command mode OFF");
         } else {
             // Start voice
-            let on_text: std::sync::Arc<dyn Fn(String) + Send + Sync> =
-                std::sync::Arc::new(move |text: String| {
-                    tracing::info!(transcription = %text, "Voice command received");
-                });
+            let (turn_tx, turn_rx) = mpsc::channel(1);
+            self.voice_turns = Some(turn_rx);
             let ts = ts.clone();
             tokio::spawn(async move {
-                if let Err(e) = ts.voice_start(config, workspace, on_text).await {
+                if let Err(e) = ts.voice_start(config, workspace, turn_tx).await {
                     tracing::warn!(error = %e, "Failed to start voice session");
                 }
             });
@@ -2226,6 +2485,23 @@ impl App {
         }
     }
 
+    /// Submit a spoken request like typed input; the reply is spoken back
+    /// once the agent completes.
+    fn submit_voice_turn(&mut self, turn: rustant_core::VoiceTurn) {
+        if self.is_processing {
+            // Dropping the turn ends the voice conversation.
+            self.push_system_msg("Voice request ignored — a task is already running");
+            return;
+        }
+        let text = turn.text.clone();
+        self.voice_turn = Some((turn, String::new()));
+        self.submit_task(&text);
+        if !self.is_processing {
+            // Handled locally (e.g. a command), so there is no reply to speak.
+            self.voice_turn = None;
+        }
+    }
+
     /// Toggle meeting recording on/off.
     fn toggle_meeting(&mut self) {
         let ts = self.toggle_state.clone();
@@ -2316,6 +2592,9 @@ impl App {
     pub fn handle_tui_event(&mut self, event: TuiEvent) {
         match event {
             TuiEvent::AssistantMessage(msg) => {
+                if let Some((_, reply)) = self.voice_turn.as_mut() {
+                    reply.clone_from(&msg);
+                }
                 // If streaming was active, finish_streaming() already pushes the
                 // accumulated text as a message. Only push a separate message if
                 // there was no streaming (non-streaming mode).
@@ -2366,6 +2645,17 @@ impl App {
                     timestamp: chrono::Utc::now().format("%H:%M:%S").to_string(),
                 });
             }
+            TuiEvent::VoiceApproval {
+                description,
+                approved,
+            } => {
+                let outcome = if approved {
+                    "confirmed"
+                } else {
+                    "not confirmed — denied"
+                };
+                self.push_system_msg(&format!("[Voice] {}: {}", description, outcome));
+            }
             TuiEvent::ToolStart { name, args } => {
                 // Create a checkpoint before write/execute tools for undo support
                 if tool_risk_level(&name) >= rustant_core::types::RiskLevel::Write {
@@ -2431,6 +2721,9 @@ impl App {
                 self.sidebar.agent_status = status;
                 match status {
                     AgentStatus::Complete | AgentStatus::Error => {
+                        if let Some((turn, reply)) = self.voice_turn.take() {
+                            turn.respond(reply);
+                        }
                         self.is_processing = false;
                         self.header.is_streaming = false;
                         self.header.tokens_used = self.agent.brain().total_usage().total();
@@ -2710,9 +3003,11 @@ impl App {
 
     /// Auto-save the current session on exit.
     fn auto_save_session(&self) {
-        if let Some(dir) = Self::sessions_dir() {
+        if let Some(dir) = Self::sessions_dir()
+            && let Ok(mgr) = crate::commands::open_sessions(&self.workspace)
+        {
             let path = dir.join("_autosave.json");
-            let _ = self.agent.memory().save_session(&path);
+            let _ = mgr.write_memory(self.agent.memory(), &path);
         }
     }
 
@@ -2725,7 +3020,9 @@ impl App {
         if !path.exists() {
             return false;
         }
-        match rustant_core::MemorySystem::load_session(&path) {
+        let loaded =
+            crate::commands::open_sessions(&self.workspace).and_then(|mgr| mgr.read_memory(&path));
+        match loaded {
             Ok(loaded) => {
                 let messages = loaded.context_messages();
                 if messages.is_empty() {
@@ -2741,6 +3038,9 @@ impl App {
                             format!("[Tool Result: {}]", output)
                         }
                         rustant_core::types::Content::MultiPart { .. } => "[MultiPart]".to_string(),
+                        rustant_core::types::Content::Attachment { handle, .. } => {
+                            handle.reference()
+                        }
                     };
                     self.conversation.push_message(DisplayMessage {
                         role: msg.role,
@@ -2789,7 +3089,7 @@ impl App {
 
     /// Save the current session to disk with a given name via SessionManager.
     fn save_session(&mut self, name: &str) {
-        let mut mgr = match rustant_core::SessionManager::new(&self.workspace) {
+        let mut mgr = match crate::commands::open_sessions(&self.workspace) {
             Ok(m) => m,
             Err(e) => {
                 self.push_system_msg(&format!("[Error] Session manager init failed: {}", e));
@@ -2798,6 +3098,19 @@ impl App {
         };
         let session_name = if name.is_empty() { None } else { Some(name) };
         let entry = mgr.start_session(session_name);
+        let persona = self.agent.active_persona().map(|p| p.name.as_str());
+        if let Err(e) = mgr.set_active_persona(persona) {
+            tracing::warn!("Failed to record persona: {}", e);
+        }
+        if let Err(e) = mgr.set_artifacts(self.agent.artifacts()) {
+            tracing::warn!("Failed to record artifacts: {}", e);
+        }
+        if let Err(e) = mgr.set_task_runs(self.agent.task_runs()) {
+            tracing::warn!("Failed to record task runs: {}", e);
+        }
+        if let Err(e) = mgr.save_recording(self.agent.recording()) {
+            tracing::warn!("Failed to save session recording: {}", e);
+        }
         let total_tokens = self.agent.brain().total_usage().total();
         match mgr.save_checkpoint(self.agent.memory(), total_tokens) {
             Ok(()) => {
@@ -2811,7 +3124,7 @@ impl App {
 
     /// Load a session from disk by name via SessionManager.
     fn load_session(&mut self, name: &str) {
-        let mut mgr = match rustant_core::SessionManager::new(&self.workspace) {
+        let mut mgr = match crate::commands::open_sessions(&self.workspace) {
             Ok(m) => m,
             Err(e) => {
                 self.push_system_msg(&format!("[Error] Session manager init failed: {}", e));
@@ -2833,6 +3146,9 @@ impl App {
                             format!("[Tool Result: {}]", output)
                         }
                         rustant_core::types::Content::MultiPart { .. } => "[MultiPart]".to_string(),
+                        rustant_core::types::Content::Attachment { handle, .. } => {
+                            handle.reference()
+                        }
                     };
                     self.conversation.push_message(DisplayMessage {
                         role: msg.role,
@@ -2844,6 +3160,9 @@ impl App {
                 }
                 // Replace agent memory with loaded memory
                 *self.agent.memory_mut() = loaded;
+                self.agent.set_artifacts(mgr.active_artifacts());
+                self.agent.set_task_runs(mgr.active_task_runs());
+                self.agent.set_recording(mgr.active_recording());
                 self.push_system_msg(&format!(
                     "Session '{}' loaded ({} messages restored).",
                     name,
@@ -2867,6 +3186,12 @@ impl App {
         self.status_bar_data.tokens_used = self.agent.brain().total_usage().total();
         self.status_bar_data.cost_usd = self.agent.brain().total_cost().total();
         self.sidebar.iteration = 0;
+        if let Some(produced) = rustant_core::summarize_artifacts(&result.artifacts) {
+            self.push_system_msg(&format!("produced: {}", produced));
+        }
+        if let Some(grants) = rustant_core::summarize_grants(&result.grants) {
+            self.push_system_msg(&format!("grants used: {}", grants));
+        }
         Ok(result)
     }
 
@@ -2884,9 +3209,25 @@ impl App {
 }
 
 /// Register tools from registry into the agent (shared logic with repl.rs).
+/// The next spoken request, or never when voice mode is off.
+async fn next_voice_turn(
+    turns: &mut Option<mpsc::Receiver<rustant_core::VoiceTurn>>,
+) -> Option<rustant_core::VoiceTurn> {
+    match turns {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
 fn register_agent_tools(agent: &mut Agent, registry: &ToolRegistry, workspace: &Path) {
-    let registry_arc = Arc::new(registry.clone());
+    agent.set_workspace(workspace.to_path_buf());
+    let mut registry = registry.clone();
+    registry.set_list_aliases(agent.config().tools.list_aliases);
+    for alias in registry.aliases().iter() {
+        agent.register_tool_alias(alias.clone());
+    }
     let tool_defs = registry.list_definitions();
+    let registry_arc = Arc::new(registry);
     for def in tool_defs {
         let name = def.name.clone();
         let ws = workspace.to_path_buf();
@@ -2912,9 +3253,13 @@ fn register_agent_tools(agent: &mut Agent, registry: &ToolRegistry, workspace: &
                     >
             }) as rustant_core::agent::ToolExecutor
         };
+        let canonical = registry_arc
+            .aliases()
+            .get(&name)
+            .map_or(name.as_str(), |alias| alias.canonical.as_str());
         agent.register_tool(RegisteredTool {
             definition: def,
-            risk_level: tool_risk_level(&name),
+            risk_level: tool_risk_level(canonical),
             executor,
         });
     }
@@ -3117,7 +3462,9 @@ fn create_tool_executor(name: &str, workspace: &Path) -> Option<rustant_core::ag
         }
         #[cfg(target_os = "macos")]
         "imessage_read" => {
-            let tool = Arc::new(rustant_tools::imessage::IMessageReadTool);
+            let tool = Arc::new(rustant_tools::imessage::IMessageReadTool::new(
+                workspace.to_path_buf(),
+            ));
             Some(Box::new(move |args| {
                 let t = tool.clone();
                 Box::pin(async move {
@@ -3690,6 +4037,16 @@ mod tests {
         assert!(last.text.contains("text"));
     }
 
+    #[test]
+    fn test_handle_command_audit_export_ocsf() {
+        let mut app = App::new(test_config(), std::env::temp_dir());
+        app.audit_store.add_trace(sample_trace());
+        app.handle_command("/audit export ocsf");
+        let last = app.conversation.messages.last().unwrap();
+        assert!(last.text.contains("\"class_uid\":6003"));
+        assert!(last.text.contains("chain: unchained"));
+    }
+
     #[test]
     fn test_handle_command_audit_export_empty() {
         let mut app = App::new(test_config(), std::env::temp_dir());
@@ -3926,6 +4283,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_handle_command_pin_fact_and_list() {
+        let mut app = App::new(test_config(), std::env::temp_dir());
+        app.handle_command("/pin The API contract is frozen");
+        assert!(
+            app.conversation
+                .messages
+                .last()
+                .unwrap()
+                .text
+                .starts_with("Pinned The API contract is frozen")
+        );
+        app.handle_command("/pins");
+        let last = app.conversation.messages.last().unwrap();
+        assert!(last.text.contains("The API contract is frozen - ~"));
+        assert!(last.text.contains("Total: ~"));
+        app.handle_command("/unpin the api");
+        assert!(app.agent.memory().pins.is_empty());
+    }
+
     #[test]
     fn test_handle_command_unpin_invalid() {
         let mut app = App::new(test_config(), std::env::temp_dir());
@@ -7,10 +7,11 @@
 //! For approval requests, a oneshot channel is used so the agent
 //! suspends until the user responds in the TUI.
 
-use rustant_core::AgentCallback;
 use rustant_core::explanation::DecisionExplanation;
 use rustant_core::safety::{ActionRequest, ApprovalDecision};
 use rustant_core::types::{AgentStatus, CostEstimate, ProgressUpdate, TokenUsage, ToolOutput};
+use rustant_core::{AgentCallback, ToggleState};
+use std::sync::Arc;
 use tokio::sync::{mpsc, oneshot};
 
 /// Events sent from the Agent (via TuiCallback) to the TUI event loop.
@@ -25,6 +26,8 @@ pub enum TuiEvent {
         action: ActionRequest,
         reply: oneshot::Sender<ApprovalDecision>,
     },
+    /// An approval for a spoken request was answered by voice.
+    VoiceApproval { description: String, approved: bool },
     /// A tool started executing.
     ToolStart {
         name: String,
@@ -97,19 +100,36 @@ pub enum TuiEvent {
 /// Implements AgentCallback by forwarding events through an mpsc channel.
 pub struct TuiCallback {
     tx: mpsc::UnboundedSender<TuiEvent>,
+    /// Voice session state; approvals for spoken requests are asked by voice.
+    toggle_state: Option<Arc<ToggleState>>,
 }
 
 impl TuiCallback {
     /// Create a new TuiCallback and its corresponding receiver.
     pub fn new() -> (Self, mpsc::UnboundedReceiver<TuiEvent>) {
         let (tx, rx) = mpsc::unbounded_channel();
-        (Self { tx }, rx)
+        (
+            Self {
+                tx,
+                toggle_state: None,
+            },
+            rx,
+        )
     }
 
     /// Create from an existing sender (useful for testing).
     #[allow(dead_code)]
     pub fn from_sender(tx: mpsc::UnboundedSender<TuiEvent>) -> Self {
-        Self { tx }
+        Self {
+            tx,
+            toggle_state: None,
+        }
+    }
+
+    /// Ask for approval by voice while a voice turn is being handled.
+    pub fn with_toggle_state(mut self, toggle_state: Arc<ToggleState>) -> Self {
+        self.toggle_state = Some(toggle_state);
+        self
     }
 }
 
@@ -126,6 +146,16 @@ impl AgentCallback for TuiCallback {
     }
 
     async fn request_approval(&self, action: &ActionRequest) -> ApprovalDecision {
+        if let Some(ref toggle_state) = self.toggle_state
+            && let Some(decision) = toggle_state.voice_approval(action).await
+        {
+            let _ = self.tx.send(TuiEvent::VoiceApproval {
+                description: action.description.clone(),
+                approved: decision == ApprovalDecision::Approve,
+            });
+            return decision;
+        }
+
         let (reply_tx, reply_rx) = oneshot::channel();
         let sent = self.tx.send(TuiEvent::ApprovalRequest {
             action: action.clone(),
@@ -244,6 +274,19 @@ impl AgentCallback for TuiCallback {
             .unwrap_or(rustant_core::plan::PlanDecision::Approve)
     }
 
+    async fn on_cost_preflight(
+        &self,
+        _estimate: &rustant_core::cost_preflight::PlanEstimate,
+        _confirm: bool,
+    ) -> rustant_core::cost_preflight::CostDecision {
+        // The user has just approved the plan in the review panel.
+        rustant_core::cost_preflight::CostDecision::Proceed
+    }
+
+    async fn on_cost_report(&self, report: &rustant_core::cost_preflight::CostReport) {
+        let _ = self.tx.send(TuiEvent::AssistantMessage(report.render()));
+    }
+
     async fn on_plan_step_start(&self, step_index: usize, step: &rustant_core::plan::PlanStep) {
         let _ = self.tx.send(TuiEvent::PlanStepStart {
             index: step_index,
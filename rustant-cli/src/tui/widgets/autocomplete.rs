@@ -175,7 +175,7 @@ impl AutocompleteState {
             })
             .collect();
 
-        scored.sort_by(|a, b| b.0.cmp(&a.0));
+        scored.sort_by_key(|b| std::cmp::Reverse(b.0));
 
         self.suggestions = scored
             .into_iter()
@@ -132,6 +132,15 @@ impl CommandPalette {
         }
     }
 
+    /// Add a command (e.g. a user-defined one) to the palette.
+    pub fn add_command(&mut self, command: &str, description: &str) {
+        self.commands.push(CommandEntry {
+            command: command.into(),
+            description: description.into(),
+        });
+        self.filtered = (0..self.commands.len()).collect();
+    }
+
     /// Activate the command palette.
     pub fn activate(&mut self, query: &str) {
         self.active = true;
@@ -374,4 +383,14 @@ mod tests {
         // Should match /cost which has "token" in description
         assert!(filtered.iter().any(|c| c.command == "/cost"));
     }
+
+    #[test]
+    fn test_add_command() {
+        let mut palette = CommandPalette::new();
+        palette.add_command("/deploy", "Deploy the current branch");
+        palette.activate("depl");
+        let filtered = palette.filtered_commands();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].description, "Deploy the current branch");
+    }
 }
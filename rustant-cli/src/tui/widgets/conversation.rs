@@ -42,6 +42,7 @@ impl From<&Message> for DisplayMessage {
                 .filter_map(|p| p.as_text())
                 .collect::<Vec<_>>()
                 .join("\n"),
+            Content::Attachment { handle, .. } => handle.reference(),
         };
 
         let tool_name = match &msg.content {
@@ -149,6 +149,7 @@ fn render_timeline(frame: &mut Frame, area: Rect, panel: &ExplanationPanel, them
                 DecisionType::ParameterChoice { tool, .. } => format!("{}:param", tool),
                 DecisionType::TaskDecomposition { .. } => "decompose".to_string(),
                 DecisionType::ErrorRecovery { .. } => "recovery".to_string(),
+                DecisionType::TaskRouting { route, .. } => route.clone(),
             };
             let time = exp.timestamp.format("%H:%M:%S");
             let confidence = format!("{:.0}%", exp.confidence * 100.0);
@@ -204,6 +205,14 @@ fn render_detail(
         DecisionType::ErrorRecovery { error, strategy } => {
             ("Error Recovery", format!("{}: {}", strategy, error))
         }
+        DecisionType::TaskRouting { route, tools } => (
+            "Task Routing",
+            if tools.is_empty() {
+                route.clone()
+            } else {
+                format!("{} ({})", route, tools.join(", "))
+            },
+        ),
     };
 
     lines.push(Line::from(vec![
@@ -335,6 +335,7 @@ mod tests {
                 message_count: 15,
                 total_messages_seen: 30,
                 pinned_count: 2,
+                pinned_tokens: 0,
                 has_summary: true,
                 facts_count: 5,
                 rules_count: 3,
@@ -58,6 +58,8 @@ pub struct StatusBarData {
     pub voice_active: bool,
     /// Whether meeting recording is active.
     pub meeting_active: bool,
+    /// Name of the active persona, if any.
+    pub persona: Option<String>,
 }
 
 /// Format a token count as a compact string (e.g., "12.4k", "1.2M").
@@ -140,6 +142,16 @@ pub fn render_status_bar(
         ));
         left_spans.push(Span::styled(" ", theme.status_bar_style()));
     }
+    if let Some(persona) = &data.persona {
+        left_spans.push(Span::styled(
+            format!(" {} ", persona),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ));
+        left_spans.push(Span::styled(" ", theme.status_bar_style()));
+    }
 
     left_spans.push(Span::styled(hints, theme.status_bar_style()));
 
@@ -0,0 +1,412 @@
+//! Approval timeout policies for unattended operation.
+//!
+//! An approval nobody answers would otherwise hold its task forever.
+//! `[safety.approval_timeouts]` sets, per approval mode and per risk level,
+//! how long an approval may wait and what happens then: deny and let the
+//! agent work around it, deny and abort the task, or escalate to a channel
+//! and apply a fallback if the escalation goes unanswered too.
+//!
+//! ```toml
+//! [safety.approval_timeouts]
+//! default = { timeout_secs = 1800, on_timeout = "deny_and_continue" }
+//! escalate_to = { channel = "telegram", destination_id = "123456" }
+//!
+//! [safety.approval_timeouts.risk_levels.destructive]
+//! timeout_secs = 600
+//! on_timeout = "escalate"
+//! escalation_window_secs = 3600
+//! fallback = "deny_and_abort"
+//! ```
+
+use crate::config::ApprovalMode;
+use crate::types::RiskLevel;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// What to do when an approval is not resolved within its window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeoutAction {
+    /// Deny the action; the agent gets a structured denial and carries on.
+    DenyAndContinue,
+    /// Deny the action and stop the task.
+    DenyAndAbort,
+    /// Send the approval to `escalate_to`, then apply `fallback` if it is
+    /// still unresolved after `escalation_window_secs`.
+    Escalate,
+}
+
+/// How an expired approval is finally resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeoutFallback {
+    #[default]
+    DenyAndContinue,
+    DenyAndAbort,
+}
+
+impl TimeoutFallback {
+    /// Whether the task stops.
+    pub fn aborts(self) -> bool {
+        self == TimeoutFallback::DenyAndAbort
+    }
+}
+
+impl std::fmt::Display for TimeoutFallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeoutFallback::DenyAndContinue => write!(f, "denied, task continued"),
+            TimeoutFallback::DenyAndAbort => write!(f, "denied, task aborted"),
+        }
+    }
+}
+
+/// One timeout policy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalTimeoutPolicy {
+    /// Seconds an approval may wait before `on_timeout` applies.
+    pub timeout_secs: u64,
+    #[serde(default = "default_on_timeout")]
+    pub on_timeout: TimeoutAction,
+    /// Seconds an escalated approval may wait before `fallback` applies.
+    #[serde(default = "default_escalation_window_secs")]
+    pub escalation_window_secs: u64,
+    /// Resolution after an unanswered escalation.
+    #[serde(default)]
+    pub fallback: TimeoutFallback,
+}
+
+fn default_on_timeout() -> TimeoutAction {
+    TimeoutAction::DenyAndContinue
+}
+
+fn default_escalation_window_secs() -> u64 {
+    900
+}
+
+/// Channel an escalated approval is sent to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EscalationTarget {
+    /// Channel name as registered in `[channels]` (e.g. "telegram").
+    pub channel: String,
+    /// Conversation/chat ID on that channel.
+    #[serde(default)]
+    pub destination_id: String,
+}
+
+/// `[safety.approval_timeouts]`. Without any policy, approvals wait
+/// indefinitely, as they always have.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalTimeoutConfig {
+    /// Policy for approvals no mode or risk-level policy covers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<ApprovalTimeoutPolicy>,
+    /// Policies by approval mode (`safe`, `cautious`, `paranoid`, `yolo`).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub modes: HashMap<String, ApprovalTimeoutPolicy>,
+    /// Policies by risk level (`read_only`, `write`, `execute`, `network`,
+    /// `destructive`). These take precedence over mode policies.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub risk_levels: HashMap<String, ApprovalTimeoutPolicy>,
+    /// Where `escalate` policies send approvals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalate_to: Option<EscalationTarget>,
+}
+
+/// Normalize a policy key so `read-only`, `read_only` and `ReadOnly` match.
+fn policy_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+impl ApprovalTimeoutConfig {
+    /// Whether any policy is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.default.is_some() || !self.modes.is_empty() || !self.risk_levels.is_empty()
+    }
+
+    /// The policy for an approval of `risk` under `mode`: the risk-level
+    /// policy, else the mode policy, else the default.
+    pub fn resolve(&self, mode: ApprovalMode, risk: RiskLevel) -> Option<ResolvedPolicy> {
+        let find = |policies: &HashMap<String, ApprovalTimeoutPolicy>, wanted: &str| {
+            let wanted = policy_key(wanted);
+            policies
+                .iter()
+                .find(|(key, _)| policy_key(key) == wanted)
+                .map(|(key, policy)| (key.clone(), policy.clone()))
+        };
+        let (source, policy) = find(&self.risk_levels, &risk.to_string())
+            .map(|(key, p)| (format!("risk_levels.{}", key), p))
+            .or_else(|| {
+                find(&self.modes, &mode.to_string()).map(|(key, p)| (format!("modes.{}", key), p))
+            })
+            .or_else(|| self.default.clone().map(|p| ("default".to_string(), p)))?;
+
+        let escalation = match policy.on_timeout {
+            TimeoutAction::Escalate => match &self.escalate_to {
+                Some(target) => Some((
+                    target.clone(),
+                    Duration::from_secs(policy.escalation_window_secs),
+                )),
+                None => {
+                    tracing::warn!(
+                        policy = %source,
+                        "Approval timeout policy escalates but no escalate_to channel is set"
+                    );
+                    None
+                }
+            },
+            _ => None,
+        };
+        let fallback = match policy.on_timeout {
+            TimeoutAction::DenyAndContinue => TimeoutFallback::DenyAndContinue,
+            TimeoutAction::DenyAndAbort => TimeoutFallback::DenyAndAbort,
+            TimeoutAction::Escalate => policy.fallback,
+        };
+        Some(ResolvedPolicy {
+            source,
+            timeout: Duration::from_secs(policy.timeout_secs),
+            escalation,
+            fallback,
+        })
+    }
+}
+
+/// The policy that governs one pending approval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedPolicy {
+    /// Which entry applied, e.g. `risk_levels.destructive` or `default`.
+    pub source: String,
+    /// How long the approval waits before it expires or escalates.
+    pub timeout: Duration,
+    /// Where to escalate and how long the escalation waits, if it does.
+    pub escalation: Option<(EscalationTarget, Duration)>,
+    /// Resolution once every window has passed.
+    pub fallback: TimeoutFallback,
+}
+
+impl ResolvedPolicy {
+    /// The latest time the approval can still be decided.
+    pub fn final_expiry(&self, created_at: DateTime<Utc>) -> DateTime<Utc> {
+        let window = self.timeout + self.escalation.as_ref().map_or(Duration::ZERO, |e| e.1);
+        created_at + chrono::Duration::from_std(window).unwrap_or(chrono::Duration::MAX)
+    }
+}
+
+/// An approval that expired without a decision, as reported in the task
+/// summary and the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ExpiredApproval {
+    pub approval_id: Uuid,
+    pub tool_name: String,
+    pub description: String,
+    pub risk_level: String,
+    pub created_at: DateTime<Utc>,
+    pub expired_at: DateTime<Utc>,
+    /// Policy entry that resolved it, e.g. `risk_levels.destructive`.
+    pub policy: String,
+    /// Whether it was escalated to a channel before expiring.
+    pub escalated: bool,
+    pub resolution: TimeoutFallback,
+}
+
+impl ExpiredApproval {
+    /// One line for summaries, e.g. "shell_exec (Run make deploy): no
+    /// decision within 30m, escalated; denied, task aborted [default]".
+    pub fn summary_line(&self) -> String {
+        let waited = (self.expired_at - self.created_at).num_minutes();
+        format!(
+            "{} ({}): no decision within {}m{}; {} [{}]",
+            self.tool_name,
+            self.description,
+            waited,
+            if self.escalated { ", escalated" } else { "" },
+            self.resolution,
+            self.policy
+        )
+    }
+}
+
+/// Text sent to the escalation channel, with reply commands that approve
+/// or deny the action.
+pub fn escalation_message(
+    approval_id: Uuid,
+    tool_name: &str,
+    description: &str,
+    risk_level: &str,
+    expires_at: DateTime<Utc>,
+) -> String {
+    let short = short_id(approval_id);
+    format!(
+        "Approval needed: {} ({} risk)\n{}\nNo decision yet; this expires at {}.\n\
+         Reply /approve {} or /deny {}",
+        tool_name,
+        risk_level,
+        description,
+        expires_at.format("%Y-%m-%d %H:%M UTC"),
+        short,
+        short
+    )
+}
+
+/// The first eight hex digits of an approval ID, used in reply commands.
+pub fn short_id(approval_id: Uuid) -> String {
+    approval_id.simple().to_string()[..8].to_string()
+}
+
+/// Parse an `/approve <id>` or `/deny <id>` reply. Returns the ID prefix
+/// and whether it approves.
+pub fn parse_approval_reply(text: &str) -> Option<(String, bool)> {
+    let mut words = text.split_whitespace();
+    let approved = match words
+        .next()?
+        .trim_start_matches('/')
+        .to_lowercase()
+        .as_str()
+    {
+        "approve" => true,
+        "deny" => false,
+        _ => return None,
+    };
+    let id = words.next()?.to_lowercase();
+    (id.len() >= 8 && id.chars().all(|c| c.is_ascii_hexdigit() || c == '-'))
+        .then(|| (id.replace('-', ""), approved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(timeout_secs: u64, on_timeout: TimeoutAction) -> ApprovalTimeoutPolicy {
+        ApprovalTimeoutPolicy {
+            timeout_secs,
+            on_timeout,
+            escalation_window_secs: 60,
+            fallback: TimeoutFallback::DenyAndAbort,
+        }
+    }
+
+    #[test]
+    fn test_no_policy_waits_indefinitely() {
+        let config = ApprovalTimeoutConfig::default();
+        assert!(!config.is_enabled());
+        assert!(
+            config
+                .resolve(ApprovalMode::Safe, RiskLevel::Destructive)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_precedence_risk_then_mode_then_default() {
+        let mut config = ApprovalTimeoutConfig {
+            default: Some(policy(600, TimeoutAction::DenyAndContinue)),
+            ..Default::default()
+        };
+        config
+            .modes
+            .insert("paranoid".into(), policy(300, TimeoutAction::DenyAndAbort));
+        config.risk_levels.insert(
+            "read-only".into(),
+            policy(60, TimeoutAction::DenyAndContinue),
+        );
+
+        let p = config
+            .resolve(ApprovalMode::Paranoid, RiskLevel::ReadOnly)
+            .unwrap();
+        assert_eq!(p.source, "risk_levels.read-only");
+        assert_eq!(p.timeout, Duration::from_secs(60));
+
+        let p = config
+            .resolve(ApprovalMode::Paranoid, RiskLevel::Write)
+            .unwrap();
+        assert_eq!(p.source, "modes.paranoid");
+        assert_eq!(p.fallback, TimeoutFallback::DenyAndAbort);
+
+        let p = config
+            .resolve(ApprovalMode::Safe, RiskLevel::Write)
+            .unwrap();
+        assert_eq!(p.source, "default");
+        assert_eq!(p.fallback, TimeoutFallback::DenyAndContinue);
+        assert!(p.escalation.is_none());
+    }
+
+    #[test]
+    fn test_escalation_needs_target() {
+        let mut config = ApprovalTimeoutConfig {
+            default: Some(policy(600, TimeoutAction::Escalate)),
+            ..Default::default()
+        };
+        let p = config
+            .resolve(ApprovalMode::Safe, RiskLevel::Write)
+            .unwrap();
+        assert!(p.escalation.is_none());
+        assert_eq!(p.fallback, TimeoutFallback::DenyAndAbort);
+
+        config.escalate_to = Some(EscalationTarget {
+            channel: "telegram".into(),
+            destination_id: "42".into(),
+        });
+        let p = config
+            .resolve(ApprovalMode::Safe, RiskLevel::Write)
+            .unwrap();
+        let (target, window) = p.escalation.clone().unwrap();
+        assert_eq!(target.channel, "telegram");
+        assert_eq!(window, Duration::from_secs(60));
+
+        let created = Utc::now();
+        assert_eq!(
+            p.final_expiry(created),
+            created + chrono::Duration::seconds(660)
+        );
+    }
+
+    #[test]
+    fn test_config_from_toml() {
+        let config: ApprovalTimeoutConfig = toml::from_str(
+            r#"
+            default = { timeout_secs = 1800 }
+            escalate_to = { channel = "slack", destination_id = "C1" }
+
+            [risk_levels.destructive]
+            timeout_secs = 600
+            on_timeout = "escalate"
+            fallback = "deny_and_abort"
+            "#,
+        )
+        .unwrap();
+        let default = config.default.as_ref().unwrap();
+        assert_eq!(default.on_timeout, TimeoutAction::DenyAndContinue);
+        let destructive = &config.risk_levels["destructive"];
+        assert_eq!(destructive.escalation_window_secs, 900);
+        assert_eq!(destructive.fallback, TimeoutFallback::DenyAndAbort);
+    }
+
+    #[test]
+    fn test_reply_parsing() {
+        let id = Uuid::new_v4();
+        let short = short_id(id);
+        assert_eq!(
+            parse_approval_reply(&format!("/approve {}", short)),
+            Some((short.clone(), true))
+        );
+        assert_eq!(
+            parse_approval_reply(&format!("deny {}", id)),
+            Some((id.simple().to_string(), false))
+        );
+        assert_eq!(parse_approval_reply("/approve"), None);
+        assert_eq!(parse_approval_reply("/approve xyz"), None);
+        assert_eq!(parse_approval_reply("sounds good"), None);
+
+        let message =
+            escalation_message(id, "shell_exec", "Run make deploy", "execute", Utc::now());
+        assert!(message.contains(&format!("/approve {}", short)));
+        assert!(message.contains(&format!("/deny {}", short)));
+    }
+}
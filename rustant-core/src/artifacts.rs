@@ -0,0 +1,429 @@
+//! Session artifacts — durable outputs produced by tools.
+//!
+//! Tools report what they produce through [`Artifact`] entries on their
+//! `ToolOutput`. The agent turns each one into an [`ArtifactRecord`] tagged
+//! with the source tool, task, and timestamp, so generated files, reports,
+//! charts, and canvas items can be listed, reopened, and summarised instead
+//! of being buried in the transcript.
+
+use crate::canvas::{CanvasMessage, CanvasTarget, ContentType};
+use crate::types::Artifact;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Broad category of an artifact, used for listing and summaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// A generated or edited file.
+    File,
+    /// A document meant for people: PDF, slides, exported report.
+    Report,
+    /// An image or chart.
+    Chart,
+    /// A bibliography export (BibTeX, RIS).
+    Bibliography,
+    /// An item pushed to the canvas.
+    Canvas,
+    /// Inline data returned by a tool.
+    Data,
+}
+
+impl std::fmt::Display for ArtifactKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArtifactKind::File => write!(f, "file"),
+            ArtifactKind::Report => write!(f, "report"),
+            ArtifactKind::Chart => write!(f, "chart"),
+            ArtifactKind::Bibliography => write!(f, "bibliography"),
+            ArtifactKind::Canvas => write!(f, "canvas"),
+            ArtifactKind::Data => write!(f, "data"),
+        }
+    }
+}
+
+/// Canvas content kept with an artifact so it can be re-pushed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CanvasArtifact {
+    pub item_id: String,
+    /// Canvas target name; empty for broadcast.
+    pub target: String,
+    pub content_type: String,
+    pub content: String,
+}
+
+/// A durable output registered during a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ArtifactRecord {
+    pub id: Uuid,
+    pub kind: ArtifactKind,
+    pub title: String,
+    /// Name of the tool that produced the artifact.
+    pub source_tool: String,
+    /// File location, resolved against the workspace when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Whether an existing file was modified rather than created.
+    #[serde(default)]
+    pub modified: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canvas: Option<CanvasArtifact>,
+    /// Task during which the artifact was produced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// Set when the file has since been deleted.
+    #[serde(default)]
+    pub stale: bool,
+}
+
+impl ArtifactRecord {
+    /// Build a record from a tool-reported artifact.
+    ///
+    /// Relative paths are resolved against `workspace`. Deletions are not
+    /// artifacts themselves; they surface as stale records instead.
+    pub fn from_artifact(
+        source_tool: &str,
+        artifact: &Artifact,
+        workspace: Option<&Path>,
+        task_id: Option<Uuid>,
+    ) -> Option<Self> {
+        let resolve = |path: &Path| match workspace {
+            Some(ws) if path.is_relative() => ws.join(path),
+            _ => path.to_path_buf(),
+        };
+        let (kind, title, path, modified, canvas) = match artifact {
+            Artifact::FileCreated { path } => (
+                classify_path(path),
+                display_name(path),
+                Some(resolve(path)),
+                false,
+                None,
+            ),
+            Artifact::FileModified { path, .. } => (
+                classify_path(path),
+                display_name(path),
+                Some(resolve(path)),
+                true,
+                None,
+            ),
+            Artifact::FileDeleted { .. } => return None,
+            Artifact::Data { mime_type, .. } if mime_type.contains("bibtex") => (
+                ArtifactKind::Bibliography,
+                "BibTeX bibliography".to_string(),
+                None,
+                false,
+                None,
+            ),
+            Artifact::Data { mime_type, .. } => {
+                (ArtifactKind::Data, mime_type.clone(), None, false, None)
+            }
+            Artifact::CanvasItem {
+                id,
+                target,
+                content_type,
+                content,
+            } => {
+                let kind = if content_type.eq_ignore_ascii_case("chart") {
+                    ArtifactKind::Chart
+                } else {
+                    ArtifactKind::Canvas
+                };
+                (
+                    kind,
+                    format!("{} canvas item {}", content_type, id),
+                    None,
+                    false,
+                    Some(CanvasArtifact {
+                        item_id: id.clone(),
+                        target: target.clone(),
+                        content_type: content_type.clone(),
+                        content: content.clone(),
+                    }),
+                )
+            }
+        };
+        Some(Self {
+            id: Uuid::new_v4(),
+            kind,
+            title,
+            source_tool: source_tool.to_string(),
+            path,
+            modified,
+            canvas,
+            task_id,
+            created_at: Utc::now(),
+            stale: false,
+        })
+    }
+
+    /// Re-check whether the backing file still exists. Returns the new stale flag.
+    pub fn refresh_stale(&mut self) -> bool {
+        self.stale = self.path.as_ref().is_some_and(|p| !p.exists());
+        self.stale
+    }
+
+    /// The canvas push that recreates this artifact, if it came from the canvas.
+    pub fn canvas_message(&self) -> Option<CanvasMessage> {
+        let canvas = self.canvas.as_ref()?;
+        let target = if canvas.target.is_empty() {
+            CanvasTarget::Broadcast
+        } else {
+            CanvasTarget::Named(canvas.target.clone())
+        };
+        Some(CanvasMessage::Push {
+            target,
+            content_type: ContentType::from_str_loose(&canvas.content_type)?,
+            content: canvas.content.clone(),
+        })
+    }
+
+    /// Open the artifact's file with the system handler, or reveal it in the
+    /// file manager when `reveal` is set.
+    pub fn open(&self, reveal: bool) -> Result<(), String> {
+        let path = self
+            .path
+            .as_ref()
+            .ok_or_else(|| format!("'{}' is not a file artifact", self.title))?;
+        if !path.exists() {
+            return Err(format!("{} no longer exists", path.display()));
+        }
+        let (program, args) = open_command(path, reveal);
+        std::process::Command::new(program)
+            .args(&args)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to launch {}: {}", program, e))
+    }
+}
+
+/// Platform command that opens (or reveals) a path.
+fn open_command(path: &Path, reveal: bool) -> (&'static str, Vec<String>) {
+    let path_str = path.display().to_string();
+    if cfg!(target_os = "macos") {
+        if reveal {
+            ("open", vec!["-R".into(), path_str])
+        } else {
+            ("open", vec![path_str])
+        }
+    } else if cfg!(target_os = "windows") {
+        if reveal {
+            ("explorer", vec![format!("/select,{}", path_str)])
+        } else {
+            ("explorer", vec![path_str])
+        }
+    } else if reveal {
+        let dir = path.parent().unwrap_or(path).display().to_string();
+        ("xdg-open", vec![dir])
+    } else {
+        ("xdg-open", vec![path_str])
+    }
+}
+
+fn display_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn classify_path(path: &Path) -> ArtifactKind {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "pdf" | "docx" | "pptx" | "odt" | "epub" => ArtifactKind::Report,
+        "png" | "jpg" | "jpeg" | "svg" | "gif" | "webp" => ArtifactKind::Chart,
+        "bib" | "ris" => ArtifactKind::Bibliography,
+        _ => ArtifactKind::File,
+    }
+}
+
+fn plural(n: usize, singular: &str, plural: &str) -> String {
+    if n == 1 {
+        format!("1 {}", singular)
+    } else {
+        format!("{} {}", n, plural)
+    }
+}
+
+/// One-line summary of what a task produced, e.g.
+/// `report.pdf, 3 modified source files, 1 chart`.
+///
+/// Repeated writes to the same file count once; a file that was created during
+/// the task counts as created even if it was modified afterwards.
+pub fn summarize_artifacts(records: &[ArtifactRecord]) -> Option<String> {
+    let mut named: Vec<String> = Vec::new();
+    let mut created_files: Vec<String> = Vec::new();
+    let mut modified = 0;
+    let mut charts = 0;
+    let mut canvas = 0;
+    let mut data = 0;
+    let mut seen: Vec<&Path> = Vec::new();
+
+    for record in records {
+        if let Some(path) = record.path.as_deref() {
+            if seen.contains(&path) {
+                continue;
+            }
+            seen.push(path);
+            let was_created = records
+                .iter()
+                .any(|r| r.path.as_deref() == Some(path) && !r.modified);
+            match record.kind {
+                ArtifactKind::Report | ArtifactKind::Bibliography => {
+                    named.push(record.title.clone())
+                }
+                ArtifactKind::Chart => charts += 1,
+                _ if was_created => created_files.push(record.title.clone()),
+                _ => modified += 1,
+            }
+            continue;
+        }
+        match record.kind {
+            ArtifactKind::Chart => charts += 1,
+            ArtifactKind::Data => data += 1,
+            ArtifactKind::Bibliography | ArtifactKind::Report => named.push(record.title.clone()),
+            _ => canvas += 1,
+        }
+    }
+
+    let mut parts = named;
+    if created_files.len() <= 3 {
+        parts.extend(created_files);
+    } else {
+        parts.push(plural(created_files.len(), "new file", "new files"));
+    }
+    if modified > 0 {
+        parts.push(plural(
+            modified,
+            "modified source file",
+            "modified source files",
+        ));
+    }
+    if charts > 0 {
+        parts.push(plural(charts, "chart", "charts"));
+    }
+    if canvas > 0 {
+        parts.push(plural(canvas, "canvas item", "canvas items"));
+    }
+    if data > 0 {
+        parts.push(plural(data, "data output", "data outputs"));
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, modified: bool, ws: &Path) -> ArtifactRecord {
+        let artifact = if modified {
+            Artifact::FileModified {
+                path: PathBuf::from(path),
+                diff: String::new(),
+            }
+        } else {
+            Artifact::FileCreated {
+                path: PathBuf::from(path),
+            }
+        };
+        ArtifactRecord::from_artifact("file_write", &artifact, Some(ws), None).unwrap()
+    }
+
+    #[test]
+    fn test_from_artifact_classifies_and_resolves() {
+        let ws = Path::new("/work");
+        let report = file("out/report.pdf", false, ws);
+        assert_eq!(report.kind, ArtifactKind::Report);
+        assert_eq!(report.title, "report.pdf");
+        assert_eq!(
+            report.path.as_deref(),
+            Some(Path::new("/work/out/report.pdf"))
+        );
+        assert_eq!(file("refs.bib", false, ws).kind, ArtifactKind::Bibliography);
+        assert!(file("src/lib.rs", true, ws).modified);
+
+        let deleted = Artifact::FileDeleted {
+            path: PathBuf::from("x"),
+        };
+        assert!(ArtifactRecord::from_artifact("file_delete", &deleted, None, None).is_none());
+    }
+
+    #[test]
+    fn test_canvas_artifact_repush() {
+        let artifact = Artifact::CanvasItem {
+            id: "abc".into(),
+            target: String::new(),
+            content_type: "chart".into(),
+            content: "{}".into(),
+        };
+        let record = ArtifactRecord::from_artifact("canvas_push", &artifact, None, None).unwrap();
+        assert_eq!(record.kind, ArtifactKind::Chart);
+        match record.canvas_message() {
+            Some(CanvasMessage::Push {
+                target,
+                content_type,
+                content,
+            }) => {
+                assert_eq!(target, CanvasTarget::Broadcast);
+                assert_eq!(content_type, ContentType::Chart);
+                assert_eq!(content, "{}");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(record.open(false).is_err());
+    }
+
+    #[test]
+    fn test_refresh_stale_for_deleted_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("notes.md"), "hi").unwrap();
+        let mut record = file("notes.md", false, dir.path());
+        assert!(!record.refresh_stale());
+        std::fs::remove_file(dir.path().join("notes.md")).unwrap();
+        assert!(record.refresh_stale());
+        assert!(record.open(true).unwrap_err().contains("no longer exists"));
+    }
+
+    #[test]
+    fn test_summarize_artifacts() {
+        let ws = Path::new("/work");
+        let chart = ArtifactRecord::from_artifact(
+            "canvas_push",
+            &Artifact::CanvasItem {
+                id: "1".into(),
+                target: String::new(),
+                content_type: "chart".into(),
+                content: String::new(),
+            },
+            None,
+            None,
+        )
+        .unwrap();
+        let records = vec![
+            file("report.pdf", false, ws),
+            file("src/a.rs", true, ws),
+            file("src/b.rs", true, ws),
+            file("src/a.rs", true, ws),
+            file("src/c.rs", true, ws),
+            chart,
+        ];
+        assert_eq!(
+            summarize_artifacts(&records).unwrap(),
+            "report.pdf, 3 modified source files, 1 chart"
+        );
+
+        // Created then modified counts as a single new file.
+        let records = vec![file("new.rs", false, ws), file("new.rs", true, ws)];
+        assert_eq!(summarize_artifacts(&records).unwrap(), "new.rs");
+        assert!(summarize_artifacts(&[]).is_none());
+    }
+}
@@ -0,0 +1,628 @@
+//! Attachments — binary tool outputs kept out of the context.
+//!
+//! Screenshots, exported charts and other binary outputs are written to a
+//! session-scoped directory and referenced by an [`AttachmentHandle`]. The
+//! context only ever holds the handle's one-line reference; tools that take
+//! an attachment as input resolve the handle back to the stored file, and
+//! image handles are turned into an [`ImageSource`] only while a request for
+//! a vision-capable provider is assembled.
+//!
+//! Images the user attaches to a message (`/attach`, a dropped file path,
+//! the clipboard) go through [`prepare_image`] first: the format is checked
+//! against what every vision provider accepts, and oversized images are
+//! downscaled before they are stored.
+//!
+//! Handles expire with the session: dropping the store deletes its directory,
+//! and directories left behind by sessions that did not shut down cleanly
+//! are swept when a new store is opened.
+
+use crate::types::{Attachment, AttachmentData, AttachmentHandle, ImageSource};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+/// Session directories untouched for this long are considered orphaned.
+const ORPHAN_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Largest attachment accepted into the store.
+pub const MAX_ATTACHMENT_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Largest image sent to a provider; Anthropic's per-image limit, the
+/// lowest of the supported providers.
+pub const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Longest image edge sent to a provider. Providers scale larger images
+/// down themselves, so sending more only costs upload time.
+pub const MAX_IMAGE_EDGE: u32 = 1568;
+
+/// File extensions treated as images when a path is dropped into the input.
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
+/// Errors raised while storing or resolving attachments.
+#[derive(Debug, thiserror::Error)]
+pub enum AttachmentError {
+    #[error("attachment '{0}' not found or expired")]
+    NotFound(String),
+    #[error("attachment '{id}' is {mime_type}, not an image")]
+    NotAnImage { id: String, mime_type: String },
+    #[error("attachment is {size} bytes; the limit is {limit}")]
+    TooLarge { size: u64, limit: u64 },
+    #[error("unsupported image format; use PNG, JPEG, GIF or WebP")]
+    UnsupportedImage,
+    #[error("image could not be decoded: {0}")]
+    InvalidImage(String),
+    #[error("no image on the clipboard: {0}")]
+    Clipboard(String),
+    #[error("attachment I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("attachment metadata is invalid: {0}")]
+    Metadata(#[from] serde_json::Error),
+}
+
+/// Directory holding every session's attachments.
+pub fn default_root() -> PathBuf {
+    std::env::temp_dir().join("rustant-attachments")
+}
+
+/// Whether a string looks like an attachment handle id.
+pub fn is_handle(value: &str) -> bool {
+    value
+        .strip_prefix("att-")
+        .is_some_and(|rest| rest.len() == 12 && rest.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Find a live attachment by id in any session under `root`.
+///
+/// Tools use this to accept handles as inputs without access to the
+/// agent's store.
+pub fn resolve_in(root: &Path, id: &str) -> Result<(AttachmentHandle, PathBuf), AttachmentError> {
+    if !is_handle(id) {
+        return Err(AttachmentError::NotFound(id.to_string()));
+    }
+    let sessions = std::fs::read_dir(root).map_err(|_| AttachmentError::NotFound(id.into()))?;
+    for session in sessions.flatten() {
+        let meta = session.path().join(format!("{}.json", id));
+        if meta.is_file() {
+            let handle: AttachmentHandle = serde_json::from_str(&std::fs::read_to_string(&meta)?)?;
+            let data = session.path().join(id);
+            if data.is_file() {
+                return Ok((handle, data));
+            }
+        }
+    }
+    Err(AttachmentError::NotFound(id.to_string()))
+}
+
+/// [`resolve_in`] against the [`default_root`].
+pub fn resolve(id: &str) -> Result<(AttachmentHandle, PathBuf), AttachmentError> {
+    resolve_in(&default_root(), id)
+}
+
+/// Read an attachment's bytes by id from the [`default_root`].
+pub fn read(id: &str) -> Result<(AttachmentHandle, Vec<u8>), AttachmentError> {
+    let (handle, path) = resolve(id)?;
+    Ok((handle, std::fs::read(path)?))
+}
+
+/// Attachments produced during one session.
+pub struct AttachmentStore {
+    dir: PathBuf,
+    handles: Vec<AttachmentHandle>,
+}
+
+impl AttachmentStore {
+    /// Open the store for `session_id` under the [`default_root`].
+    pub fn new(session_id: Uuid) -> Self {
+        Self::with_root(&default_root(), session_id)
+    }
+
+    /// Open the store for `session_id` under `root`, sweeping orphaned
+    /// session directories.
+    pub fn with_root(root: &Path, session_id: Uuid) -> Self {
+        sweep_orphans(root);
+        Self {
+            dir: root.join(session_id.to_string()),
+            handles: Vec::new(),
+        }
+    }
+
+    /// Directory backing this session's attachments.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Move a tool's attachment into the store and return its handle.
+    pub fn store(&mut self, attachment: Attachment) -> Result<AttachmentHandle, AttachmentError> {
+        let bytes = match attachment.data {
+            AttachmentData::Bytes(bytes) => bytes,
+            AttachmentData::File(path) => {
+                let size = std::fs::metadata(&path)?.len();
+                check_size(size)?;
+                std::fs::read(&path)?
+            }
+        };
+        check_size(bytes.len() as u64)?;
+        std::fs::create_dir_all(&self.dir)?;
+
+        let id = format!("att-{}", &Uuid::new_v4().simple().to_string()[..12]);
+        let dimensions = image_dimensions(&bytes);
+        let thumbnail_text = attachment
+            .thumbnail_text
+            .or_else(|| dimensions.map(|(w, h)| format!("{}×{} px", w, h)));
+        let handle = AttachmentHandle {
+            id: id.clone(),
+            mime_type: attachment.mime_type,
+            size: bytes.len() as u64,
+            description: attachment.description,
+            thumbnail_text,
+            dimensions,
+        };
+        std::fs::write(self.dir.join(&id), &bytes)?;
+        std::fs::write(
+            self.dir.join(format!("{}.json", id)),
+            serde_json::to_string(&handle)?,
+        )?;
+        self.handles.push(handle.clone());
+        Ok(handle)
+    }
+
+    /// Validate and downscale an image the user attached, then store it.
+    pub fn store_image(
+        &mut self,
+        bytes: Vec<u8>,
+        description: impl Into<String>,
+    ) -> Result<AttachmentHandle, AttachmentError> {
+        let image = prepare_image(bytes)?;
+        let mut thumbnail = format!("{}×{} px", image.dimensions.0, image.dimensions.1);
+        if let Some((w, h)) = image.downscaled_from {
+            thumbnail.push_str(&format!(", downscaled from {}×{}", w, h));
+        }
+        self.store(
+            Attachment::bytes(image.mime_type, description, image.bytes)
+                .with_thumbnail_text(thumbnail),
+        )
+    }
+
+    /// Handles stored in this session, oldest first.
+    pub fn handles(&self) -> &[AttachmentHandle] {
+        &self.handles
+    }
+
+    /// Look up a handle stored in this session.
+    pub fn get(&self, id: &str) -> Option<&AttachmentHandle> {
+        self.handles.iter().find(|h| h.id == id)
+    }
+
+    /// Read the bytes behind a handle stored in this session.
+    pub fn read(&self, id: &str) -> Result<Vec<u8>, AttachmentError> {
+        if self.get(id).is_none() {
+            return Err(AttachmentError::NotFound(id.to_string()));
+        }
+        std::fs::read(self.dir.join(id)).map_err(|_| AttachmentError::NotFound(id.to_string()))
+    }
+
+    /// Resolve an image handle into request-ready image data.
+    pub fn image_source(&self, id: &str) -> Result<ImageSource, AttachmentError> {
+        let handle = self
+            .get(id)
+            .ok_or_else(|| AttachmentError::NotFound(id.to_string()))?;
+        if !handle.is_image() {
+            return Err(AttachmentError::NotAnImage {
+                id: id.to_string(),
+                mime_type: handle.mime_type.clone(),
+            });
+        }
+        Ok(ImageSource {
+            media_type: handle.mime_type.clone(),
+            data: BASE64.encode(self.read(id)?),
+        })
+    }
+
+    /// Delete every attachment of this session; their handles expire.
+    pub fn clear(&mut self) {
+        self.handles.clear();
+        if self.dir.exists()
+            && let Err(e) = std::fs::remove_dir_all(&self.dir)
+        {
+            tracing::warn!(dir = %self.dir.display(), error = %e, "Failed to remove attachments");
+        }
+    }
+}
+
+impl Drop for AttachmentStore {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+fn check_size(size: u64) -> Result<(), AttachmentError> {
+    if size > MAX_ATTACHMENT_BYTES {
+        return Err(AttachmentError::TooLarge {
+            size,
+            limit: MAX_ATTACHMENT_BYTES,
+        });
+    }
+    Ok(())
+}
+
+/// Remove session directories that have not been written to for a day.
+fn sweep_orphans(root: &Path) {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > ORPHAN_AGE);
+        if stale {
+            let _ = std::fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// MIME type of an image every vision provider accepts, sniffed from its
+/// leading bytes.
+pub fn image_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// Pixel dimensions of a PNG, JPEG, GIF or WebP image, read from its header.
+pub fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    match image_mime_type(bytes)? {
+        "image/png" if bytes.len() >= 24 => {
+            let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+            let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+            Some((width, height))
+        }
+        "image/gif" if bytes.len() >= 10 => {
+            let width = u16::from_le_bytes([bytes[6], bytes[7]]) as u32;
+            let height = u16::from_le_bytes([bytes[8], bytes[9]]) as u32;
+            Some((width, height))
+        }
+        "image/jpeg" => jpeg_dimensions(bytes),
+        "image/webp" => webp_dimensions(bytes),
+        _ => None,
+    }
+}
+
+/// Walk JPEG segments to the first start-of-frame marker.
+fn jpeg_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut pos = 2;
+    while pos + 4 <= bytes.len() {
+        if bytes[pos] != 0xFF {
+            return None;
+        }
+        let marker = bytes[pos + 1];
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        let len = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let is_sof = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if is_sof && pos + 9 <= bytes.len() {
+            let height = u16::from_be_bytes([bytes[pos + 5], bytes[pos + 6]]) as u32;
+            let width = u16::from_be_bytes([bytes[pos + 7], bytes[pos + 8]]) as u32;
+            return Some((width, height));
+        }
+        pos += 2 + len;
+    }
+    None
+}
+
+/// Read the canvas size from a WebP's VP8, VP8L or VP8X chunk.
+fn webp_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let chunk = bytes.get(12..16)?;
+    let data = bytes.get(20..)?;
+    match chunk {
+        b"VP8 " if data.len() >= 10 => {
+            let width = u16::from_le_bytes([data[6], data[7]]) & 0x3FFF;
+            let height = u16::from_le_bytes([data[8], data[9]]) & 0x3FFF;
+            Some((width as u32, height as u32))
+        }
+        b"VP8L" if data.len() >= 5 => {
+            let bits = u32::from_le_bytes(data[1..5].try_into().ok()?);
+            Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+        }
+        b"VP8X" if data.len() >= 10 => {
+            let width = u32::from_le_bytes([data[4], data[5], data[6], 0]) + 1;
+            let height = u32::from_le_bytes([data[7], data[8], data[9], 0]) + 1;
+            Some((width, height))
+        }
+        _ => None,
+    }
+}
+
+/// An image checked and, if needed, downscaled for a provider request.
+#[derive(Debug, Clone)]
+pub struct PreparedImage {
+    pub bytes: Vec<u8>,
+    pub mime_type: &'static str,
+    pub dimensions: (u32, u32),
+    /// Original dimensions, when the image was downscaled.
+    pub downscaled_from: Option<(u32, u32)>,
+}
+
+/// Check that `bytes` are an image every vision provider accepts, and
+/// downscale it when its longest edge exceeds [`MAX_IMAGE_EDGE`] or its size
+/// exceeds [`MAX_IMAGE_BYTES`].
+pub fn prepare_image(bytes: Vec<u8>) -> Result<PreparedImage, AttachmentError> {
+    check_size(bytes.len() as u64)?;
+    let mime_type = image_mime_type(&bytes).ok_or(AttachmentError::UnsupportedImage)?;
+    let dimensions = match image_dimensions(&bytes) {
+        Some(dimensions) => dimensions,
+        None => {
+            let image = decode(&bytes)?;
+            (image.width(), image.height())
+        }
+    };
+    let fits = dimensions.0.max(dimensions.1) <= MAX_IMAGE_EDGE;
+    if fits && bytes.len() as u64 <= MAX_IMAGE_BYTES {
+        return Ok(PreparedImage {
+            bytes,
+            mime_type,
+            dimensions,
+            downscaled_from: None,
+        });
+    }
+
+    let image = decode(&bytes)?;
+    // JPEG stays JPEG; everything else is re-encoded losslessly as PNG.
+    let (format, mime_type) = if mime_type == "image/jpeg" {
+        (image::ImageFormat::Jpeg, "image/jpeg")
+    } else {
+        (image::ImageFormat::Png, "image/png")
+    };
+    let mut edge = MAX_IMAGE_EDGE.min(dimensions.0.max(dimensions.1));
+    loop {
+        let scaled = image.resize(edge, edge, image::imageops::FilterType::Lanczos3);
+        let scaled = match format {
+            image::ImageFormat::Jpeg => image::DynamicImage::ImageRgb8(scaled.to_rgb8()),
+            _ => scaled,
+        };
+        let mut out = std::io::Cursor::new(Vec::new());
+        scaled
+            .write_to(&mut out, format)
+            .map_err(|e| AttachmentError::InvalidImage(e.to_string()))?;
+        let out = out.into_inner();
+        if out.len() as u64 <= MAX_IMAGE_BYTES || edge <= 256 {
+            return Ok(PreparedImage {
+                bytes: out,
+                mime_type,
+                dimensions: (scaled.width(), scaled.height()),
+                downscaled_from: Some(dimensions),
+            });
+        }
+        edge = edge * 3 / 4;
+    }
+}
+
+fn decode(bytes: &[u8]) -> Result<image::DynamicImage, AttachmentError> {
+    image::load_from_memory(bytes).map_err(|e| AttachmentError::InvalidImage(e.to_string()))
+}
+
+/// Pull image file paths that a terminal pasted into `input` when a file
+/// was dragged onto it. Terminals paste absolute paths, quoted or with
+/// backslash-escaped spaces, or `file://` URLs; only paths to existing image
+/// files are taken. Returns the remaining text and the paths found.
+pub fn take_dropped_images(input: &str) -> (String, Vec<PathBuf>) {
+    let mut rest = String::new();
+    let mut paths = Vec::new();
+    for (raw, token) in shell_words(input) {
+        match dropped_image_path(&token) {
+            Some(path) => paths.push(path),
+            None => {
+                if !rest.is_empty() {
+                    rest.push(' ');
+                }
+                rest.push_str(raw);
+            }
+        }
+    }
+    if paths.is_empty() {
+        return (input.to_string(), paths);
+    }
+    (rest, paths)
+}
+
+fn dropped_image_path(token: &str) -> Option<PathBuf> {
+    let token = token.strip_prefix("file://").unwrap_or(token);
+    let path = match token.strip_prefix("~/") {
+        Some(rest) => directories::BaseDirs::new()?.home_dir().join(rest),
+        None => PathBuf::from(token),
+    };
+    let is_image = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+    (path.is_absolute() && is_image && path.is_file()).then_some(path)
+}
+
+/// Split on unquoted whitespace, honouring single and double quotes and
+/// backslash escapes. Yields each word's raw text and its unescaped value.
+fn shell_words(input: &str) -> Vec<(&str, String)> {
+    let mut words = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut word = String::new();
+        let mut quote = None;
+        let mut end = input.len();
+        while let Some(&(i, c)) = chars.peek() {
+            match (quote, c) {
+                (None, c) if c.is_whitespace() => {
+                    end = i;
+                    break;
+                }
+                (None, '\'' | '"') => quote = Some(c),
+                (Some(q), c) if c == q => quote = None,
+                (q, '\\') if q != Some('\'') => {
+                    chars.next();
+                    if let Some(&(_, escaped)) = chars.peek() {
+                        word.push(escaped);
+                    }
+                }
+                (_, c) => word.push(c),
+            }
+            chars.next();
+        }
+        words.push((&input[start..end], word));
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn test_store_and_resolve_by_handle() {
+        let root = tempfile::TempDir::new().unwrap();
+        let mut store = AttachmentStore::with_root(root.path(), Uuid::new_v4());
+        let handle = store
+            .store(Attachment::bytes("image/png", "Screenshot", png(1440, 900)))
+            .unwrap();
+
+        assert!(is_handle(&handle.id));
+        assert_eq!(handle.thumbnail_text.as_deref(), Some("1440×900 px"));
+        assert!(
+            handle
+                .reference()
+                .starts_with(&format!("[attachment {}:", handle.id))
+        );
+
+        let (resolved, path) = resolve_in(root.path(), &handle.id).unwrap();
+        assert_eq!(resolved, handle);
+        assert_eq!(std::fs::read(path).unwrap(), png(1440, 900));
+
+        let image = store.image_source(&handle.id).unwrap();
+        assert_eq!(image.media_type, "image/png");
+        assert_eq!(BASE64.decode(image.data).unwrap(), png(1440, 900));
+    }
+
+    #[test]
+    fn test_file_attachment_is_copied() {
+        let root = tempfile::TempDir::new().unwrap();
+        let source = root.path().join("report.pdf");
+        std::fs::write(&source, b"%PDF-1.7").unwrap();
+        let mut store = AttachmentStore::with_root(&root.path().join("store"), Uuid::new_v4());
+        let handle = store
+            .store(Attachment::file("application/pdf", "Report", &source))
+            .unwrap();
+
+        std::fs::remove_file(&source).unwrap();
+        assert_eq!(store.read(&handle.id).unwrap(), b"%PDF-1.7");
+        assert!(matches!(
+            store.image_source(&handle.id),
+            Err(AttachmentError::NotAnImage { .. })
+        ));
+    }
+
+    #[test]
+    fn test_handles_expire_with_the_store() {
+        let root = tempfile::TempDir::new().unwrap();
+        let mut store = AttachmentStore::with_root(root.path(), Uuid::new_v4());
+        let handle = store
+            .store(Attachment::bytes("text/plain", "Log", b"hello".to_vec()))
+            .unwrap();
+        let dir = store.dir().to_path_buf();
+        assert!(dir.exists());
+
+        drop(store);
+        assert!(!dir.exists());
+        assert!(matches!(
+            resolve_in(root.path(), &handle.id),
+            Err(AttachmentError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_image_headers() {
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00];
+        jpeg.extend_from_slice(&[0xFF, 0xC0, 0x00, 0x11, 0x08, 0x02, 0x58, 0x03, 0x20]);
+        assert_eq!(image_mime_type(&jpeg), Some("image/jpeg"));
+        assert_eq!(image_dimensions(&jpeg), Some((800, 600)));
+
+        let mut webp = b"RIFF\0\0\0\0WEBPVP8X\0\0\0\0".to_vec();
+        webp.extend_from_slice(&[0, 0, 0, 0, 0x7F, 0x07, 0x00, 0x37, 0x04, 0x00]);
+        assert_eq!(image_mime_type(&webp), Some("image/webp"));
+        assert_eq!(image_dimensions(&webp), Some((1920, 1080)));
+
+        assert_eq!(image_mime_type(b"%PDF-1.7"), None);
+    }
+
+    #[test]
+    fn test_prepare_image_validates_and_downscales() {
+        assert!(matches!(
+            prepare_image(b"not an image".to_vec()),
+            Err(AttachmentError::UnsupportedImage)
+        ));
+
+        let small = png(2, 2);
+        let prepared = prepare_image(small.clone()).unwrap();
+        assert_eq!(prepared.bytes, small);
+        assert_eq!(prepared.downscaled_from, None);
+
+        let mut large = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::new_rgb8(3000, 1500)
+            .write_to(&mut large, image::ImageFormat::Png)
+            .unwrap();
+        let prepared = prepare_image(large.into_inner()).unwrap();
+        assert_eq!(prepared.mime_type, "image/png");
+        assert_eq!(prepared.dimensions, (MAX_IMAGE_EDGE, MAX_IMAGE_EDGE / 2));
+        assert_eq!(prepared.downscaled_from, Some((3000, 1500)));
+        assert_eq!(image_dimensions(&prepared.bytes), Some(prepared.dimensions));
+    }
+
+    #[test]
+    fn test_take_dropped_images() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let shot = dir.path().join("Screen Shot.png");
+        std::fs::write(&shot, png(10, 10)).unwrap();
+        let quoted = format!("what is wrong here '{}'", shot.display());
+        let (text, paths) = take_dropped_images(&quoted);
+        assert_eq!(text, "what is wrong here");
+        assert_eq!(paths, vec![shot.clone()]);
+
+        let escaped = format!("{} explain", shot.display().to_string().replace(' ', "\\ "));
+        let (text, paths) = take_dropped_images(&escaped);
+        assert_eq!(text, "explain");
+        assert_eq!(paths, vec![shot]);
+
+        let plain = "don't touch design/mockup.png or /missing/file.png";
+        assert_eq!(take_dropped_images(plain), (plain.to_string(), vec![]));
+    }
+
+    #[test]
+    fn test_is_handle() {
+        assert!(is_handle("att-0123456789ab"));
+        assert!(!is_handle("att-xyz"));
+        assert!(!is_handle("/tmp/screenshot.png"));
+    }
+}
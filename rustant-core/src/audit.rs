@@ -4,12 +4,13 @@
 //! tool execution records, and token/cost tracking into a single
 //! queryable, exportable trace.
 
-use crate::merkle::{MerkleChain, VerificationResult};
-use crate::safety::AuditEvent;
+use crate::merkle::{MerkleChain, VerificationResult, hex_sha256};
+use crate::safety::{AuditEntry, AuditEvent};
 use crate::types::{CostEstimate, RiskLevel, TokenUsage};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::Write;
 use std::path::Path;
 use uuid::Uuid;
 
@@ -84,6 +85,22 @@ pub enum TraceEventKind {
 }
 
 impl TraceEventKind {
+    /// Every tag returned by [`TraceEventKind::type_tag`], for validating
+    /// event-type filters.
+    pub const TYPE_TAGS: [&'static str; 11] = [
+        "task_started",
+        "task_completed",
+        "tool_requested",
+        "tool_approved",
+        "tool_denied",
+        "approval_requested",
+        "approval_decision",
+        "tool_executed",
+        "llm_call",
+        "status_change",
+        "error",
+    ];
+
     /// Convert a safety-layer [`AuditEvent`] into the corresponding
     /// [`TraceEventKind`].
     pub fn from_audit_event(event: &AuditEvent) -> Self {
@@ -122,11 +139,62 @@ impl TraceEventKind {
                 tool: tool.clone(),
                 approved: *approved,
             },
+            AuditEvent::ContractViolation {
+                tool,
+                contract,
+                condition,
+                detail,
+            } => TraceEventKind::ToolDenied {
+                tool: tool.clone(),
+                reason: format!("contract '{}' {}: {}", contract, condition, detail),
+            },
+            AuditEvent::CapabilityBlocked {
+                tool,
+                capability,
+                target,
+                reason,
+            } => TraceEventKind::ToolDenied {
+                tool: tool.clone(),
+                reason: format!("paranoid {} '{}': {}", capability, target, reason),
+            },
+            AuditEvent::CapabilityGranted { tool, .. } => {
+                TraceEventKind::ToolApproved { tool: tool.clone() }
+            }
+            AuditEvent::DevicePairing { action, device, .. } => TraceEventKind::StatusChange {
+                from: format!("device {}", device),
+                to: action.clone(),
+            },
+            AuditEvent::AgentViolation {
+                agent_id,
+                tool,
+                limit,
+                detail,
+            } => TraceEventKind::ToolDenied {
+                tool: tool.clone(),
+                reason: format!("agent {} {}: {}", agent_id, limit, detail),
+            },
+            AuditEvent::ClientDisconnected { client, .. } => TraceEventKind::StatusChange {
+                from: format!("client {}", client),
+                to: "disconnected".to_string(),
+            },
+            AuditEvent::ApprovalExpired {
+                tool,
+                policy,
+                resolution,
+                ..
+            } => TraceEventKind::ToolDenied {
+                tool: tool.clone(),
+                reason: format!("approval expired under {}: {}", policy, resolution),
+            },
+            AuditEvent::FormSubmitted { title, client, .. } => TraceEventKind::StatusChange {
+                from: format!("form {}", title),
+                to: format!("submitted by {}", client),
+            },
         }
     }
 
     /// Return the event type as a human-readable tag (e.g. `"tool_requested"`).
-    fn type_tag(&self) -> &'static str {
+    pub fn type_tag(&self) -> &'static str {
         match self {
             TraceEventKind::TaskStarted { .. } => "task_started",
             TraceEventKind::TaskCompleted { .. } => "task_completed",
@@ -522,6 +590,85 @@ impl AuditStore {
     pub fn is_empty(&self) -> bool {
         self.traces.is_empty()
     }
+
+    /// Build an unchained store from the safety guardian's audit log, with
+    /// one trace per session and the original event timestamps preserved.
+    pub fn from_audit_log<'a>(entries: impl IntoIterator<Item = &'a AuditEntry>) -> Self {
+        let mut store = Self::new();
+        for entry in entries {
+            let index = match store
+                .traces
+                .iter()
+                .position(|t| t.session_id == entry.session_id)
+            {
+                Some(index) => index,
+                None => {
+                    store.traces.push(ExecutionTrace {
+                        trace_id: entry.session_id,
+                        session_id: entry.session_id,
+                        task_id: Uuid::nil(),
+                        goal: "safety audit log".to_string(),
+                        started_at: entry.timestamp,
+                        completed_at: None,
+                        success: None,
+                        iterations: 0,
+                        events: Vec::new(),
+                        total_usage: TokenUsage::default(),
+                        total_cost: CostEstimate::default(),
+                    });
+                    store.traces.len() - 1
+                }
+            };
+            let trace = &mut store.traces[index];
+            trace.events.push(TraceEvent {
+                sequence: trace.events.len(),
+                timestamp: entry.timestamp,
+                kind: TraceEventKind::from_audit_event(&entry.event),
+            });
+        }
+        store
+    }
+
+    /// Check the given traces, which must belong to this store, against the
+    /// Merkle chain: each trace must still hash to its own node and that
+    /// node's link must verify.
+    pub fn chain_status(&self, traces: &[&ExecutionTrace]) -> ChainStatus {
+        let Some(chain) = &self.merkle_chain else {
+            return ChainStatus::Unchained;
+        };
+        // Evicted traces keep their nodes, so stored traces sit at the tail.
+        let offset = chain.len().saturating_sub(self.traces.len());
+        for trace in traces {
+            let broken = |reason: String| ChainStatus::Broken {
+                trace_id: trace.trace_id,
+                reason,
+            };
+            let Some(position) = self
+                .traces
+                .iter()
+                .position(|t| t.trace_id == trace.trace_id)
+            else {
+                return broken("trace is not in this store".to_string());
+            };
+            let index = offset + position;
+            let Some(node) = chain.nodes().get(index) else {
+                return broken(format!("no chain node for trace at index {}", index));
+            };
+            if !chain.verify_node(index) {
+                return broken(format!("chain link broken at node {}", index));
+            }
+            let hash = serde_json::to_vec(trace)
+                .map(|bytes| hex_sha256(&bytes))
+                .unwrap_or_default();
+            if hash != node.event_hash {
+                return broken(format!("trace content does not match node {}", index));
+            }
+        }
+        ChainStatus::Verified {
+            checked: traces.len(),
+            root_hash: chain.root_hash().map(str::to_string),
+        }
+    }
 }
 
 impl Default for AuditStore {
@@ -552,6 +699,9 @@ pub struct AuditQuery {
     pub success_only: Option<bool>,
     pub since: Option<DateTime<Utc>>,
     pub until: Option<DateTime<Utc>>,
+    /// Event type tags (see [`TraceEventKind::TYPE_TAGS`]) kept by SIEM
+    /// exports. Empty keeps every type.
+    pub event_types: Vec<String>,
 }
 
 impl AuditQuery {
@@ -599,9 +749,49 @@ impl AuditQuery {
         self
     }
 
+    pub fn for_event_type(mut self, tag: impl Into<String>) -> Self {
+        self.event_types.push(tag.into());
+        self
+    }
+
     /// Determine whether a given [`ExecutionTrace`] satisfies every non-`None`
     /// predicate in this query.
     fn matches(&self, trace: &ExecutionTrace) -> bool {
+        if !self.matches_trace_fields(trace) {
+            return false;
+        }
+        if let Some(since) = self.since
+            && trace.started_at < since
+        {
+            return false;
+        }
+        if let Some(until) = self.until
+            && trace.started_at > until
+        {
+            return false;
+        }
+        true
+    }
+
+    /// Determine whether a single event falls inside the time range and
+    /// event-type filter. Exports filter per event so a long-running trace
+    /// contributes only the part of it inside the range.
+    fn matches_event(&self, event: &TraceEvent) -> bool {
+        if let Some(since) = self.since
+            && event.timestamp < since
+        {
+            return false;
+        }
+        if let Some(until) = self.until
+            && event.timestamp > until
+        {
+            return false;
+        }
+        self.event_types.is_empty() || self.event_types.iter().any(|t| t == event.kind.type_tag())
+    }
+
+    /// Every trace-level predicate except the time range.
+    fn matches_trace_fields(&self, trace: &ExecutionTrace) -> bool {
         if let Some(sid) = self.session_id
             && trace.session_id != sid
         {
@@ -639,16 +829,6 @@ impl AuditQuery {
                 _ => return false,
             }
         }
-        if let Some(since) = self.since
-            && trace.started_at < since
-        {
-            return false;
-        }
-        if let Some(until) = self.until
-            && trace.started_at > until
-        {
-            return false;
-        }
         true
     }
 }
@@ -755,6 +935,516 @@ fn csv_escape(value: &str) -> String {
     }
 }
 
+// ---------------------------------------------------------------------------
+// SIEM export (OCSF + CEF)
+// ---------------------------------------------------------------------------
+
+/// OCSF schema version emitted by [`AuditExporter::write_ocsf`].
+pub const OCSF_VERSION: &str = "1.1.0";
+
+/// Line-oriented formats accepted by SIEM collectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SiemFormat {
+    /// OCSF events as JSON Lines.
+    Ocsf,
+    /// ArcSight Common Event Format, one event per line.
+    Cef,
+}
+
+impl SiemFormat {
+    /// Parse a format name as typed on the command line.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "ocsf" => Some(SiemFormat::Ocsf),
+            "cef" => Some(SiemFormat::Cef),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the exported traces still match the store's Merkle chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ChainStatus {
+    /// Every exported trace hashes to its node and every link verifies.
+    Verified {
+        checked: usize,
+        root_hash: Option<String>,
+    },
+    /// The first exported trace that failed verification.
+    Broken { trace_id: Uuid, reason: String },
+    /// The store keeps no Merkle chain, so the export makes no integrity claim.
+    Unchained,
+}
+
+impl std::fmt::Display for ChainStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChainStatus::Verified { checked, .. } => {
+                write!(f, "verified ({} traces)", checked)
+            }
+            ChainStatus::Broken { trace_id, reason } => {
+                write!(f, "BROKEN at trace {}: {}", trace_id, reason)
+            }
+            ChainStatus::Unchained => write!(f, "unchained"),
+        }
+    }
+}
+
+/// Describes one SIEM export. Consumers should check `chain` before
+/// trusting the segment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportMetadata {
+    pub format: SiemFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ocsf_version: Option<String>,
+    pub exported_at: DateTime<Utc>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub event_types: Vec<String>,
+    /// Traces that contributed at least one event.
+    pub traces: usize,
+    pub events: usize,
+    pub chain: ChainStatus,
+}
+
+/// Parse an export time bound: RFC 3339, a `YYYY-MM-DD` date (midnight UTC),
+/// or an age relative to `now` such as `30m`, `12h` or `7d`.
+pub fn parse_time_bound(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc());
+    }
+    let split = value
+        .len()
+        .checked_sub(1)
+        .filter(|&i| value.is_char_boundary(i))?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().ok()?;
+    let age = match unit {
+        "m" => chrono::Duration::minutes(amount),
+        "h" => chrono::Duration::hours(amount),
+        "d" => chrono::Duration::days(amount),
+        _ => return None,
+    };
+    Some(now - age)
+}
+
+/// An OCSF event class and the category it belongs to.
+struct OcsfClass {
+    uid: u32,
+    name: &'static str,
+    category_uid: u32,
+    category_name: &'static str,
+}
+
+const BASE_EVENT: OcsfClass = OcsfClass {
+    uid: 0,
+    name: "Base Event",
+    category_uid: 0,
+    category_name: "Uncategorized",
+};
+
+const DETECTION_FINDING: OcsfClass = OcsfClass {
+    uid: 2004,
+    name: "Detection Finding",
+    category_uid: 2,
+    category_name: "Findings",
+};
+
+const AUTHORIZE_SESSION: OcsfClass = OcsfClass {
+    uid: 3003,
+    name: "Authorize Session",
+    category_uid: 3,
+    category_name: "Identity & Access Management",
+};
+
+const API_ACTIVITY: OcsfClass = OcsfClass {
+    uid: 6003,
+    name: "API Activity",
+    category_uid: 6,
+    category_name: "Application Activity",
+};
+
+const SUCCESS: (u32, &str) = (1, "Success");
+const FAILURE: (u32, &str) = (2, "Failure");
+
+/// Keys of a serialized [`TraceEventKind`] that OCSF export maps to typed
+/// attributes; everything else goes under `unmapped`.
+const OCSF_MAPPED_KEYS: [&str; 4] = ["type", "tool", "success", "duration_ms"];
+
+/// One trace event classified for SIEM export. OCSF and CEF render from
+/// the same classification so the two formats agree on class and severity.
+struct SiemRecord<'a> {
+    trace: &'a ExecutionTrace,
+    event: &'a TraceEvent,
+    operator: &'a str,
+    class: &'static OcsfClass,
+    activity_id: u32,
+    activity_name: &'static str,
+    status: Option<(u32, &'static str)>,
+    severity_id: u32,
+    risk: Option<RiskLevel>,
+}
+
+impl<'a> SiemRecord<'a> {
+    fn new(trace: &'a ExecutionTrace, event: &'a TraceEvent, operator: &'a str) -> Self {
+        let outcome = |ok: bool| if ok { SUCCESS } else { FAILURE };
+        // An execution inherits the risk of the request that preceded it.
+        let risk = match &event.kind {
+            TraceEventKind::ToolRequested { risk_level, .. } => Some(*risk_level),
+            TraceEventKind::ToolExecuted { tool, .. } => trace
+                .events
+                .iter()
+                .take_while(|e| e.sequence < event.sequence)
+                .filter_map(|e| match &e.kind {
+                    TraceEventKind::ToolRequested {
+                        tool: requested,
+                        risk_level,
+                        ..
+                    } if requested == tool => Some(*risk_level),
+                    _ => None,
+                })
+                .last(),
+            _ => None,
+        };
+        let (api_id, api_name) = match risk {
+            Some(RiskLevel::ReadOnly) => (2, "Read"),
+            Some(RiskLevel::Write) => (3, "Update"),
+            Some(RiskLevel::Destructive) => (4, "Delete"),
+            _ => (99, "Other"),
+        };
+        let (class, activity_id, activity_name, status, severity_id) = match &event.kind {
+            TraceEventKind::ToolRequested { .. } => {
+                (&API_ACTIVITY, api_id, api_name, Some((99, "Requested")), 1)
+            }
+            TraceEventKind::ToolExecuted { success, .. } => (
+                &API_ACTIVITY,
+                api_id,
+                api_name,
+                Some(outcome(*success)),
+                if *success { 1 } else { 2 },
+            ),
+            TraceEventKind::ToolApproved { .. } => {
+                (&AUTHORIZE_SESSION, 99, "Approve", Some(SUCCESS), 1)
+            }
+            TraceEventKind::ApprovalRequested { .. } => (
+                &AUTHORIZE_SESSION,
+                99,
+                "Request Approval",
+                Some((99, "Pending")),
+                1,
+            ),
+            TraceEventKind::ApprovalDecision { approved, .. } => (
+                &AUTHORIZE_SESSION,
+                99,
+                if *approved { "Approve" } else { "Deny" },
+                Some(outcome(*approved)),
+                1,
+            ),
+            TraceEventKind::ToolDenied { .. } => {
+                (&DETECTION_FINDING, 1, "Create", Some((1, "New")), 3)
+            }
+            TraceEventKind::TaskStarted { .. } => (&BASE_EVENT, 99, "Task Started", None, 1),
+            TraceEventKind::TaskCompleted { success, .. } => (
+                &BASE_EVENT,
+                99,
+                "Task Completed",
+                Some(outcome(*success)),
+                if *success { 1 } else { 2 },
+            ),
+            TraceEventKind::LlmCall { .. } => (&BASE_EVENT, 99, "LLM Call", None, 1),
+            TraceEventKind::StatusChange { .. } => (&BASE_EVENT, 99, "Status Change", None, 1),
+            TraceEventKind::Error { .. } => (&BASE_EVENT, 99, "Error", Some(FAILURE), 2),
+        };
+        Self {
+            trace,
+            event,
+            operator,
+            class,
+            activity_id,
+            activity_name,
+            status,
+            severity_id,
+            risk,
+        }
+    }
+
+    /// Stable per-event identifier shared by both formats.
+    fn uid(&self) -> String {
+        format!("{}:{}", self.trace.trace_id, self.event.sequence)
+    }
+
+    fn to_ocsf(&self) -> Result<serde_json::Value, AuditError> {
+        use serde_json::{Value, json};
+
+        let raw = serde_json::to_value(&self.event.kind)
+            .map_err(|e| AuditError::SerializationFailed(e.to_string()))?;
+        let mut unmapped = serde_json::Map::new();
+        unmapped.insert("event_type".into(), json!(self.event.kind.type_tag()));
+        unmapped.insert("sequence".into(), json!(self.event.sequence));
+        unmapped.insert("task_id".into(), json!(self.trace.task_id));
+        if let Value::Object(fields) = &raw {
+            for (key, value) in fields {
+                if !OCSF_MAPPED_KEYS.contains(&key.as_str()) {
+                    unmapped.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        let mut event = json!({
+            "activity_id": self.activity_id,
+            "activity_name": self.activity_name,
+            "category_uid": self.class.category_uid,
+            "category_name": self.class.category_name,
+            "class_uid": self.class.uid,
+            "class_name": self.class.name,
+            "type_uid": self.class.uid * 100 + self.activity_id,
+            "type_name": format!("{}: {}", self.class.name, self.activity_name),
+            "severity_id": self.severity_id,
+            "severity": ocsf_severity_name(self.severity_id),
+            "time": self.event.timestamp.timestamp_millis(),
+            "message": self.event.kind.summary(),
+            "metadata": {
+                "version": OCSF_VERSION,
+                "uid": self.uid(),
+                "correlation_uid": self.trace.trace_id.to_string(),
+                "log_name": "rustant-audit",
+                "product": {
+                    "name": "Rustant",
+                    "vendor_name": "Rustant",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            },
+            "actor": {
+                "app_name": "rustant",
+                "session": { "uid": self.trace.session_id.to_string() },
+            },
+            "unmapped": unmapped,
+            "raw_data": raw.to_string(),
+        });
+        if let Some((status_id, status)) = self.status {
+            event["status_id"] = json!(status_id);
+            event["status"] = json!(status);
+        }
+
+        match &self.event.kind {
+            TraceEventKind::ToolRequested { tool, .. }
+            | TraceEventKind::ToolExecuted { tool, .. } => {
+                event["api"] = json!({
+                    "operation": tool,
+                    "service": { "name": "rustant-tools" },
+                });
+                event["src_endpoint"] = json!({ "name": "rustant" });
+                event["cloud"] = json!({ "provider": "local" });
+                if let TraceEventKind::ToolExecuted { duration_ms, .. } = &self.event.kind {
+                    event["duration"] = json!(duration_ms);
+                }
+            }
+            TraceEventKind::ToolApproved { .. }
+            | TraceEventKind::ApprovalRequested { .. }
+            | TraceEventKind::ApprovalDecision { .. } => {
+                let user = json!({ "name": self.operator, "type_id": 1, "type": "User" });
+                event["actor"]["user"] = user.clone();
+                event["user"] = user;
+                event["session"] = json!({ "uid": self.trace.session_id.to_string() });
+            }
+            TraceEventKind::ToolDenied { tool, reason } => {
+                event["finding_info"] = json!({
+                    "uid": self.uid(),
+                    "title": format!("Tool call blocked: {}", tool),
+                    "desc": reason,
+                    "types": ["Rustant safety block"],
+                });
+            }
+            _ => {}
+        }
+        Ok(event)
+    }
+
+    fn to_cef(&self) -> String {
+        let mut ext: Vec<(&str, String)> = vec![
+            ("rt", self.event.timestamp.timestamp_millis().to_string()),
+            ("cat", self.class.name.to_string()),
+            ("act", self.activity_name.to_string()),
+            ("externalId", self.uid()),
+            ("cs1Label", "traceId".to_string()),
+            ("cs1", self.trace.trace_id.to_string()),
+            ("cs2Label", "sessionId".to_string()),
+            ("cs2", self.trace.session_id.to_string()),
+        ];
+        if let Some(tool) = self.event.kind.tool_name() {
+            ext.push(("cs3Label", "tool".to_string()));
+            ext.push(("cs3", tool.to_string()));
+        }
+        if let Some(risk) = self.risk {
+            ext.push(("cs4Label", "riskLevel".to_string()));
+            ext.push(("cs4", risk.to_string()));
+        }
+        ext.push(("cs5Label", "taskId".to_string()));
+        ext.push(("cs5", self.trace.task_id.to_string()));
+        if let Some((_, status)) = self.status {
+            ext.push(("outcome", status.to_ascii_lowercase()));
+        }
+        match &self.event.kind {
+            TraceEventKind::ToolExecuted { duration_ms, .. } => {
+                ext.push(("cn1Label", "durationMs".to_string()));
+                ext.push(("cn1", duration_ms.to_string()));
+            }
+            TraceEventKind::ToolDenied { reason, .. } => ext.push(("reason", reason.clone())),
+            TraceEventKind::ToolApproved { .. }
+            | TraceEventKind::ApprovalRequested { .. }
+            | TraceEventKind::ApprovalDecision { .. } => {
+                ext.push(("suser", self.operator.to_string()))
+            }
+            _ => {}
+        }
+        let (_, details) = self.event.kind.csv_details();
+        if !details.is_empty() {
+            ext.push(("msg", details));
+        }
+
+        let extension: Vec<String> = ext
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, cef_escape_extension(value)))
+            .collect();
+        format!(
+            "CEF:0|Rustant|Rustant|{}|{}|{}|{}|{}",
+            cef_escape_header(env!("CARGO_PKG_VERSION")),
+            self.event.kind.type_tag(),
+            cef_escape_header(&self.event.kind.summary()),
+            cef_severity(self.severity_id),
+            extension.join(" "),
+        )
+    }
+}
+
+fn ocsf_severity_name(severity_id: u32) -> &'static str {
+    match severity_id {
+        1 => "Informational",
+        2 => "Low",
+        3 => "Medium",
+        4 => "High",
+        5 => "Critical",
+        6 => "Fatal",
+        99 => "Other",
+        _ => "Unknown",
+    }
+}
+
+/// Map an OCSF severity onto CEF's 0–10 scale.
+fn cef_severity(severity_id: u32) -> u32 {
+    match severity_id {
+        1 => 1,
+        2 => 3,
+        3 => 5,
+        4 => 7,
+        5 => 9,
+        6 => 10,
+        _ => 0,
+    }
+}
+
+/// Escape a CEF header field: backslashes and pipes are escaped, and line
+/// breaks (not allowed in headers) become spaces.
+fn cef_escape_header(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+/// Escape a CEF extension value: backslashes, equals signs and line breaks.
+fn cef_escape_extension(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\r', "\\r")
+        .replace('\n', "\\n")
+}
+
+/// Name recorded as the approving user on approval events.
+fn operator_name() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+impl AuditExporter {
+    /// Stream the events selected by `query` to `out` as OCSF JSON Lines.
+    pub fn write_ocsf<W: Write>(
+        store: &AuditStore,
+        query: &AuditQuery,
+        out: W,
+    ) -> Result<ExportMetadata, AuditError> {
+        Self::write_siem(store, query, SiemFormat::Ocsf, out)
+    }
+
+    /// Stream the events selected by `query` to `out` as CEF lines.
+    pub fn write_cef<W: Write>(
+        store: &AuditStore,
+        query: &AuditQuery,
+        out: W,
+    ) -> Result<ExportMetadata, AuditError> {
+        Self::write_siem(store, query, SiemFormat::Cef, out)
+    }
+
+    /// Stream the events selected by `query` one line at a time, so large
+    /// ranges never build the whole export in memory. Returns metadata
+    /// including the Merkle chain status of the traces that were exported.
+    pub fn write_siem<W: Write>(
+        store: &AuditStore,
+        query: &AuditQuery,
+        format: SiemFormat,
+        out: W,
+    ) -> Result<ExportMetadata, AuditError> {
+        let io_err = |e: std::io::Error| AuditError::IoError(e.to_string());
+        let operator = operator_name();
+        let mut out = std::io::BufWriter::new(out);
+        let mut exported = Vec::new();
+        let mut events = 0;
+
+        for trace in store
+            .traces
+            .iter()
+            .filter(|t| query.matches_trace_fields(t))
+        {
+            let before = events;
+            for event in trace.events.iter().filter(|e| query.matches_event(e)) {
+                let record = SiemRecord::new(trace, event, &operator);
+                match format {
+                    SiemFormat::Ocsf => {
+                        serde_json::to_writer(&mut out, &record.to_ocsf()?)
+                            .map_err(|e| AuditError::SerializationFailed(e.to_string()))?;
+                        out.write_all(b"\n").map_err(io_err)?;
+                    }
+                    SiemFormat::Cef => writeln!(out, "{}", record.to_cef()).map_err(io_err)?,
+                }
+                events += 1;
+            }
+            if events > before {
+                exported.push(trace);
+            }
+        }
+        out.flush().map_err(io_err)?;
+
+        Ok(ExportMetadata {
+            format,
+            ocsf_version: (format == SiemFormat::Ocsf).then(|| OCSF_VERSION.to_string()),
+            exported_at: Utc::now(),
+            since: query.since,
+            until: query.until,
+            event_types: query.event_types.clone(),
+            traces: exported.len(),
+            events,
+            chain: store.chain_status(&exported),
+        })
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Analytics helpers
 // ---------------------------------------------------------------------------
@@ -1857,4 +2547,204 @@ mod tests {
         let err = AuditError::TraceNotFound(id);
         assert_eq!(err.to_string(), format!("trace not found: {}", id));
     }
+
+    fn export_lines(store: &AuditStore, query: &AuditQuery, format: SiemFormat) -> Vec<String> {
+        let mut buf = Vec::new();
+        AuditExporter::write_siem(store, query, format, &mut buf).unwrap();
+        String::from_utf8(buf)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    // 38
+    #[test]
+    fn test_ocsf_class_mapping() {
+        let mut trace = make_trace_with_tools();
+        trace.push_event(TraceEventKind::ToolDenied {
+            tool: "shell_exec".into(),
+            reason: "rm -rf outside workspace".into(),
+        });
+        let mut store = AuditStore::new();
+        store.add_trace(trace);
+        let events: Vec<serde_json::Value> =
+            export_lines(&store, &AuditQuery::new(), SiemFormat::Ocsf)
+                .iter()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+        assert_eq!(events.len(), 7);
+
+        let executed = &events[3];
+        assert_eq!(executed["class_uid"], 6003);
+        assert_eq!(executed["activity_id"], 2); // read-only request → Read
+        assert_eq!(executed["type_uid"], 600302);
+        assert_eq!(executed["status_id"], 1);
+        assert_eq!(executed["api"]["operation"], "file_read");
+        assert_eq!(executed["duration"], 42);
+        assert_eq!(executed["metadata"]["version"], OCSF_VERSION);
+        assert_eq!(executed["unmapped"]["output_preview"], "fn main() ...");
+        assert!(executed["unmapped"].get("tool").is_none());
+        assert!(
+            executed["raw_data"]
+                .as_str()
+                .unwrap()
+                .contains("tool_executed")
+        );
+
+        assert_eq!(events[2]["class_uid"], 3003);
+        assert!(events[2]["user"]["name"].is_string());
+        assert_eq!(events[4]["class_uid"], 0);
+        assert_eq!(events[5]["severity_id"], 2);
+
+        let denied = &events[6];
+        assert_eq!(denied["class_uid"], 2004);
+        assert_eq!(denied["severity"], "Medium");
+        assert_eq!(denied["finding_info"]["desc"], "rm -rf outside workspace");
+    }
+
+    // 39
+    #[test]
+    fn test_cef_output_escaping() {
+        let mut trace = make_trace("a|b");
+        trace.push_event(TraceEventKind::ToolDenied {
+            tool: "shell_exec".into(),
+            reason: "x=1\nC:\\tmp".into(),
+        });
+        let mut store = AuditStore::new();
+        store.add_trace(trace);
+        let lines = export_lines(&store, &AuditQuery::new(), SiemFormat::Cef);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("|task_started|Task started: a\\|b|1|"));
+        assert!(lines[1].starts_with("CEF:0|Rustant|Rustant|"));
+        assert!(lines[1].contains("|tool_denied|"));
+        assert!(lines[1].contains("|5|rt="));
+        assert!(lines[1].contains("cat=Detection Finding"));
+        assert!(lines[1].contains("cs3Label=tool cs3=shell_exec"));
+        assert!(lines[1].contains("reason=x\\=1\\nC:\\\\tmp"));
+    }
+
+    // 40
+    #[test]
+    fn test_siem_export_filters_events() {
+        let mut trace = make_trace_with_tools();
+        let base = trace.started_at;
+        for (i, event) in trace.events.iter_mut().enumerate() {
+            event.timestamp = base + Duration::minutes(i as i64);
+        }
+        let mut store = AuditStore::new();
+        store.add_trace(trace);
+
+        let query = AuditQuery::new().for_event_type("tool_executed");
+        let lines = export_lines(&store, &query, SiemFormat::Cef);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("|tool_executed|"));
+
+        let query = AuditQuery::new()
+            .since(base + Duration::minutes(1))
+            .until(base + Duration::minutes(2));
+        let mut buf = Vec::new();
+        let meta = AuditExporter::write_ocsf(&store, &query, &mut buf).unwrap();
+        assert_eq!(meta.events, 2);
+        assert_eq!(meta.traces, 1);
+        assert_eq!(meta.ocsf_version.as_deref(), Some(OCSF_VERSION));
+        assert_eq!(meta.chain, ChainStatus::Unchained);
+
+        let query = AuditQuery::new().for_event_type("status_change");
+        let meta = AuditExporter::write_cef(&store, &query, std::io::sink()).unwrap();
+        assert_eq!(meta.events, 0);
+        assert_eq!(meta.traces, 0);
+    }
+
+    // 41
+    #[test]
+    fn test_siem_export_chain_status() {
+        let mut store = AuditStore::with_merkle_chain();
+        store.add_trace(make_trace("first"));
+        store.add_trace(make_trace("second"));
+        let meta = AuditExporter::write_ocsf(&store, &AuditQuery::new(), std::io::sink()).unwrap();
+        match meta.chain {
+            ChainStatus::Verified { checked, root_hash } => {
+                assert_eq!(checked, 2);
+                assert_eq!(root_hash, store.merkle_root_hash());
+            }
+            other => panic!("expected verified chain, got {:?}", other),
+        }
+
+        let tampered = store.traces[1].trace_id;
+        store.traces[1].goal = "rewritten".into();
+        let meta = AuditExporter::write_cef(&store, &AuditQuery::new(), std::io::sink()).unwrap();
+        match meta.chain {
+            ChainStatus::Broken { trace_id, .. } => assert_eq!(trace_id, tampered),
+            other => panic!("expected broken chain, got {:?}", other),
+        }
+
+        // A range that excludes the tampered trace still verifies.
+        let first = store.traces[0].session_id;
+        let query = AuditQuery::new().for_session(first);
+        let meta = AuditExporter::write_cef(&store, &query, std::io::sink()).unwrap();
+        assert!(matches!(
+            meta.chain,
+            ChainStatus::Verified { checked: 1, .. }
+        ));
+    }
+
+    // 42
+    #[test]
+    fn test_audit_store_from_audit_log() {
+        let session_a = Uuid::new_v4();
+        let session_b = Uuid::new_v4();
+        let entry = |session_id, event| AuditEntry {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            session_id,
+            event,
+        };
+        let log = [
+            entry(
+                session_a,
+                AuditEvent::ActionApproved {
+                    tool: "file_read".into(),
+                },
+            ),
+            entry(
+                session_b,
+                AuditEvent::ActionDenied {
+                    tool: "shell_exec".into(),
+                    reason: "blocked".into(),
+                },
+            ),
+            entry(
+                session_a,
+                AuditEvent::ActionExecuted {
+                    tool: "file_read".into(),
+                    success: true,
+                    duration_ms: 5,
+                },
+            ),
+        ];
+        let store = AuditStore::from_audit_log(&log);
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.traces()[0].session_id, session_a);
+        assert_eq!(store.traces()[0].events.len(), 2);
+        assert_eq!(store.traces()[0].events[1].sequence, 1);
+        assert_eq!(store.traces()[0].events[1].timestamp, log[2].timestamp);
+        assert!(store.merkle_chain().is_none());
+    }
+
+    // 43
+    #[test]
+    fn test_parse_time_bound() {
+        let now = Utc::now();
+        assert_eq!(parse_time_bound("2h", now), Some(now - Duration::hours(2)));
+        assert_eq!(parse_time_bound("7d", now), Some(now - Duration::days(7)));
+        let dt = parse_time_bound("2026-01-02T03:04:05Z", now).unwrap();
+        assert_eq!(dt.to_rfc3339(), "2026-01-02T03:04:05+00:00");
+        let day = parse_time_bound("2026-01-02", now).unwrap();
+        assert_eq!(day.to_rfc3339(), "2026-01-02T00:00:00+00:00");
+        assert!(parse_time_bound("soon", now).is_none());
+        assert!(parse_time_bound("", now).is_none());
+        assert_eq!(SiemFormat::parse("OCSF"), Some(SiemFormat::Ocsf));
+        assert_eq!(SiemFormat::parse("csv"), None);
+    }
 }
@@ -3,16 +3,17 @@
 //! Defines the `LlmProvider` trait for model-agnostic LLM interactions,
 //! and provides an OpenAI-compatible implementation with streaming support.
 
+use crate::cache::{CachePolicy, CacheStats, ResponseCache};
 use crate::error::LlmError;
 use crate::types::{
-    CompletionRequest, CompletionResponse, Content, CostEstimate, Message, Role, StreamEvent,
-    TokenUsage, ToolDefinition,
+    AttachmentHandle, CompletionRequest, CompletionResponse, Content, CostEstimate, Message, Role,
+    StreamEvent, TokenUsage, ToolDefinition,
 };
 use async_trait::async_trait;
 use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use tracing::{debug, info, warn};
+use tracing::{Instrument, debug, info, warn};
 
 /// Trait for LLM providers, supporting both full and streaming completions.
 #[async_trait]
@@ -36,38 +37,229 @@ pub trait LlmProvider: Send + Sync {
     /// Return whether this provider supports tool/function calling.
     fn supports_tools(&self) -> bool;
 
+    /// Return whether the model accepts image input. Image attachments are
+    /// only resolved into request bytes for providers that return `true`.
+    fn supports_vision(&self) -> bool {
+        false
+    }
+
     /// Return the cost per token (input, output) in USD.
     fn cost_per_token(&self) -> (f64, f64);
 
     /// Return the model name.
     fn model_name(&self) -> &str;
+
+    /// Count `text` with the provider's own tokenizer, if it exposes one.
+    ///
+    /// Used to calibrate [`TokenCounter`] for models whose tokenizer isn't
+    /// available locally. Returns `None` when unsupported or on error.
+    async fn count_tokens(&self, _text: &str) -> Option<usize> {
+        None
+    }
+}
+
+/// Which tokenizer produced a token count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizerKind {
+    /// An OpenAI BPE encoding (e.g. `o200k_base`) — exact for OpenAI models.
+    Bpe(&'static str),
+    /// `cl100k_base` standing in for a tokenizer that isn't available
+    /// locally (Claude, Gemini, local models). Typically within ~15%.
+    Approximate(&'static str),
+    /// Reported by the provider's own tokenizer (see [`LlmProvider::count_tokens`]).
+    Provider,
+    /// ~4 characters per token. Last resort when no encoding could be loaded.
+    Heuristic,
+}
+
+impl TokenizerKind {
+    /// Whether counts from this tokenizer are only estimates.
+    pub fn is_estimate(&self) -> bool {
+        matches!(self, Self::Approximate(_) | Self::Heuristic)
+    }
+}
+
+impl std::fmt::Display for TokenizerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bpe(encoding) => write!(f, "{}", encoding),
+            Self::Approximate(family) => write!(f, "cl100k_base (approximate for {})", family),
+            Self::Provider => write!(f, "provider tokenizer"),
+            Self::Heuristic => write!(f, "~4 chars/token (heuristic)"),
+        }
+    }
 }
 
-/// Token counter using tiktoken-rs for accurate BPE tokenization.
+/// A token count together with the tokenizer that produced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenCount {
+    pub tokens: usize,
+    pub tokenizer: TokenizerKind,
+}
+
+/// Texts shorter than this are cheaper to tokenize than to hash and look up.
+const TOKEN_CACHE_MIN_BYTES: usize = 256;
+/// Entries kept before the token cache is cleared.
+const TOKEN_CACHE_CAPACITY: usize = 4096;
+
+/// Process-wide token counts keyed by (model, content hash). System prompts
+/// and tool schemas are counted on every iteration, by the brain and the
+/// provider alike, so they only pay for tokenization once.
+static TOKEN_CACHE: std::sync::LazyLock<
+    std::sync::Mutex<std::collections::HashMap<(String, u64), TokenCount>>,
+> = std::sync::LazyLock::new(Default::default);
+
+fn content_hash(text: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Token counter selecting the most accurate tokenizer available for a model.
+///
+/// OpenAI models use their own BPE encoding. Other vendors' tokenizers are
+/// approximated with `cl100k_base` until the provider reports an exact count
+/// (recorded with [`TokenCounter::record`], e.g. by [`Brain::calibrate_token_counts`]).
 pub struct TokenCounter {
-    bpe: tiktoken_rs::CoreBPE,
+    model: String,
+    kind: TokenizerKind,
+    bpe: Option<&'static tiktoken_rs::CoreBPE>,
 }
 
 impl TokenCounter {
     /// Create a token counter for the given model.
     /// Falls back to cl100k_base if the model isn't recognized.
     pub fn for_model(model: &str) -> Self {
-        let bpe = tiktoken_rs::get_bpe_from_model(model).unwrap_or_else(|_| {
-            tiktoken_rs::cl100k_base().expect("cl100k_base should be available")
-        });
-        Self { bpe }
+        use tiktoken_rs::tokenizer::{Tokenizer, get_tokenizer};
+
+        let lower = model.to_lowercase();
+        let encoding = match get_tokenizer(&lower) {
+            Some(Tokenizer::O200kBase | Tokenizer::O200kHarmony) => {
+                Some(("o200k_base", tiktoken_rs::o200k_base_singleton()))
+            }
+            Some(Tokenizer::Cl100kBase) => {
+                Some(("cl100k_base", tiktoken_rs::cl100k_base_singleton()))
+            }
+            Some(Tokenizer::P50kBase | Tokenizer::P50kEdit) => {
+                Some(("p50k_base", tiktoken_rs::p50k_base_singleton()))
+            }
+            Some(Tokenizer::R50kBase | Tokenizer::Gpt2) => {
+                Some(("r50k_base", tiktoken_rs::r50k_base_singleton()))
+            }
+            None => None,
+        };
+        let (kind, bpe) = match encoding {
+            Some((name, bpe)) => (TokenizerKind::Bpe(name), bpe),
+            None => {
+                let family = if lower.contains("claude") {
+                    "claude"
+                } else if lower.contains("gemini") {
+                    "gemini"
+                } else {
+                    "local"
+                };
+                (
+                    TokenizerKind::Approximate(family),
+                    tiktoken_rs::cl100k_base_singleton(),
+                )
+            }
+        };
+        Self {
+            model: model.to_string(),
+            kind,
+            bpe: Some(bpe),
+        }
+    }
+
+    /// Create a token counter for a model listed by a provider.
+    pub fn for_model_info(info: &crate::providers::ModelInfo) -> Self {
+        Self::for_model(&info.id)
+    }
+
+    /// A counter using the ~4 characters per token heuristic.
+    pub fn heuristic(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            kind: TokenizerKind::Heuristic,
+            bpe: None,
+        }
+    }
+
+    /// The tokenizer this counter uses when no provider count is cached.
+    pub fn tokenizer(&self) -> TokenizerKind {
+        self.kind
+    }
+
+    /// Count tokens in a string, reporting which tokenizer produced the count.
+    pub fn count_detailed(&self, text: &str) -> TokenCount {
+        let cacheable = text.len() >= TOKEN_CACHE_MIN_BYTES;
+        let key = (self.model.clone(), content_hash(text));
+        if cacheable
+            && let Ok(cache) = TOKEN_CACHE.lock()
+            && let Some(hit) = cache.get(&key)
+        {
+            return *hit;
+        }
+
+        let count = match self.bpe {
+            Some(bpe) => TokenCount {
+                tokens: bpe.encode_with_special_tokens(text).len(),
+                tokenizer: self.kind,
+            },
+            None => TokenCount {
+                tokens: text.len().div_ceil(4),
+                tokenizer: TokenizerKind::Heuristic,
+            },
+        };
+        if cacheable {
+            insert_cached(key, count);
+        }
+        count
+    }
+
+    /// Record an exact count reported by the provider for `text`. Later
+    /// counts of the same text for this model return it instead.
+    pub fn record(&self, text: &str, tokens: usize) {
+        insert_cached(
+            (self.model.clone(), content_hash(text)),
+            TokenCount {
+                tokens,
+                tokenizer: TokenizerKind::Provider,
+            },
+        );
+    }
+
+    /// Whether `text` already has a provider-reported count for this model.
+    pub fn has_provider_count(&self, text: &str) -> bool {
+        TOKEN_CACHE
+            .lock()
+            .map(|cache| {
+                cache
+                    .get(&(self.model.clone(), content_hash(text)))
+                    .is_some_and(|c| c.tokenizer == TokenizerKind::Provider)
+            })
+            .unwrap_or(false)
     }
 
     /// Count the number of tokens in a string.
     pub fn count(&self, text: &str) -> usize {
-        self.bpe.encode_with_special_tokens(text).len()
+        self.count_detailed(text).tokens
     }
 
     /// Estimate the token count for a set of tool definitions.
     ///
     /// Each tool definition adds overhead for the JSON schema structure
     /// (type/function wrapper), plus the name, description, and parameters.
+    /// A provider-reported count for the whole set takes precedence.
     pub fn count_tool_definitions(&self, tools: &[ToolDefinition]) -> usize {
+        if tools.is_empty() {
+            return 0;
+        }
+        let schema = tool_schema_text(tools);
+        if self.has_provider_count(&schema) {
+            return self.count(&schema);
+        }
         let mut total = 0;
         for tool in tools {
             total += 10; // struct overhead (type, function wrapper, required fields)
@@ -81,44 +273,146 @@ impl TokenCounter {
     /// Estimate the token count for a set of messages.
     /// Adds overhead for message structure (role, separators).
     pub fn count_messages(&self, messages: &[Message]) -> usize {
-        let mut total = 0;
-        for msg in messages {
-            // Each message has overhead: role token + separators (~4 tokens)
-            total += 4;
-            match &msg.content {
-                Content::Text { text } => total += self.count(text),
-                Content::ToolCall {
-                    name, arguments, ..
-                } => {
-                    total += self.count(name);
-                    total += self.count(&arguments.to_string());
-                }
-                Content::ToolResult { output, .. } => {
-                    total += self.count(output);
-                }
-                Content::MultiPart { parts } => {
-                    for part in parts {
-                        match part {
-                            Content::Text { text } => total += self.count(text),
-                            Content::ToolCall {
-                                name, arguments, ..
-                            } => {
-                                total += self.count(name);
-                                total += self.count(&arguments.to_string());
-                            }
-                            Content::ToolResult { output, .. } => {
-                                total += self.count(output);
-                            }
-                            _ => total += 10,
+        messages
+            .iter()
+            .map(|m| self.count_message(m))
+            .sum::<usize>()
+            + 3 // reply priming overhead
+    }
+
+    /// Estimate the token count for one message, including ~4 tokens of
+    /// role and separator overhead.
+    pub fn count_message(&self, msg: &Message) -> usize {
+        let mut total = 4;
+        match &msg.content {
+            Content::Text { text } => total += self.count(text),
+            Content::ToolCall {
+                name, arguments, ..
+            } => {
+                total += self.count(name);
+                total += self.count(&arguments.to_string());
+            }
+            Content::ToolResult { output, .. } => {
+                total += self.count(output);
+            }
+            Content::MultiPart { parts } => {
+                for part in parts {
+                    match part {
+                        Content::Text { text } => total += self.count(text),
+                        Content::ToolCall {
+                            name, arguments, ..
+                        } => {
+                            total += self.count(name);
+                            total += self.count(&arguments.to_string());
+                        }
+                        Content::ToolResult { output, .. } => {
+                            total += self.count(output);
+                        }
+                        Content::Attachment { handle, image } => {
+                            total += self.count_attachment(handle, image.is_some());
                         }
+                        _ => total += 10,
                     }
                 }
             }
+            Content::Attachment { handle, image } => {
+                total += self.count_attachment(handle, image.is_some());
+            }
+        }
+        total
+    }
+
+    /// Tokens for an attachment reference, plus the image itself once it
+    /// has been resolved for a vision request.
+    fn count_attachment(&self, handle: &AttachmentHandle, resolved: bool) -> usize {
+        let image = if resolved {
+            self.image_tokens(handle)
+        } else {
+            0
+        };
+        self.count(&handle.reference()) + image
+    }
+
+    /// Input tokens the model's provider bills for sending `handle` as an
+    /// image.
+    pub fn image_tokens(&self, handle: &AttachmentHandle) -> usize {
+        match handle.dimensions {
+            Some((width, height)) => ImagePricing::for_model(&self.model).tokens(width, height),
+            None => IMAGE_TOKEN_ESTIMATE,
+        }
+    }
+}
+
+/// Rough token cost of one image in a vision request whose dimensions are
+/// unknown; providers bill a full-size screenshot at around this many tokens.
+const IMAGE_TOKEN_ESTIMATE: usize = 1_600;
+
+/// How a provider bills image input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImagePricing {
+    /// Anthropic: width × height / 750, after scaling the long edge to 1568 px.
+    Anthropic,
+    /// OpenAI (high detail): 85 + 170 per 512 px tile, after fitting the
+    /// image in 2048 px and scaling the short side to 768 px.
+    OpenAi,
+    /// Gemini: 258 for images up to 384 px, else 258 per 768 px tile.
+    Gemini,
+}
+
+impl ImagePricing {
+    /// The pricing model of the provider serving `model`.
+    pub fn for_model(model: &str) -> Self {
+        let model = model.to_lowercase();
+        if model.contains("claude") {
+            Self::Anthropic
+        } else if model.contains("gemini") {
+            Self::Gemini
+        } else {
+            Self::OpenAi
+        }
+    }
+
+    /// Input tokens billed for one `width` × `height` image.
+    pub fn tokens(&self, width: u32, height: u32) -> usize {
+        let (w, h) = (width.max(1) as f64, height.max(1) as f64);
+        match self {
+            Self::Anthropic => {
+                let scale = (1568.0 / w.max(h)).min(1.0);
+                ((w * scale) * (h * scale) / 750.0).ceil() as usize
+            }
+            Self::OpenAi => {
+                let fit = (2048.0 / w.max(h)).min(1.0);
+                let (w, h) = (w * fit, h * fit);
+                let short = (768.0 / w.min(h)).min(1.0);
+                let (w, h) = (w * short, h * short);
+                let tiles = (w / 512.0).ceil() * (h / 512.0).ceil();
+                85 + 170 * tiles as usize
+            }
+            Self::Gemini => {
+                if w <= 384.0 && h <= 384.0 {
+                    258
+                } else {
+                    258 * ((w / 768.0).ceil() * (h / 768.0).ceil()) as usize
+                }
+            }
         }
-        total + 3 // reply priming overhead
     }
 }
 
+fn insert_cached(key: (String, u64), count: TokenCount) {
+    if let Ok(mut cache) = TOKEN_CACHE.lock() {
+        if cache.len() >= TOKEN_CACHE_CAPACITY && !cache.contains_key(&key) {
+            cache.clear();
+        }
+        cache.insert(key, count);
+    }
+}
+
+/// Serialized tool schemas, as counted for calibration.
+fn tool_schema_text(tools: &[ToolDefinition]) -> String {
+    serde_json::to_string(tools).unwrap_or_default()
+}
+
 /// Sanitize tool_call → tool_result ordering in a message sequence.
 ///
 /// This runs provider-agnostically *before* messages are sent to any LLM provider,
@@ -256,6 +550,14 @@ pub struct Brain {
     token_counter: TokenCounter,
     /// Optional knowledge addendum appended to system prompt from distilled rules.
     knowledge_addendum: String,
+    /// Prompt fragment for the active persona, placed before the knowledge addendum.
+    persona_prompt: String,
+    /// Pinned files and facts, appended last and refreshed before each request.
+    pinned_context: String,
+    /// Shared LLM response cache, when enabled.
+    cache: Option<Arc<ResponseCache>>,
+    /// Cache lookups since the last `reset_cache_task_stats()`.
+    cache_task_stats: CacheStats,
 }
 
 impl Brain {
@@ -268,14 +570,106 @@ impl Brain {
             total_cost: CostEstimate::default(),
             token_counter: TokenCounter::for_model(&model_name),
             knowledge_addendum: String::new(),
+            persona_prompt: String::new(),
+            pinned_context: String::new(),
+            cache: None,
+            cache_task_stats: CacheStats::default(),
         }
     }
 
+    /// Serve completions from `cache` when possible (`None` disables caching).
+    pub fn set_response_cache(&mut self, cache: Option<Arc<ResponseCache>>) {
+        self.cache = cache;
+    }
+
+    /// The response cache in use, if any.
+    pub fn response_cache(&self) -> Option<&Arc<ResponseCache>> {
+        self.cache.as_ref()
+    }
+
+    /// Cache lookups made by this brain since the last task reset.
+    pub fn cache_task_stats(&self) -> CacheStats {
+        self.cache_task_stats
+    }
+
+    /// Reset the per-task cache counters (called at the start of each task).
+    pub fn reset_cache_task_stats(&mut self) {
+        self.cache_task_stats = CacheStats::default();
+    }
+
+    /// Set the active persona's prompt fragment (empty to clear).
+    pub fn set_persona_prompt(&mut self, fragment: String) {
+        self.persona_prompt = fragment;
+    }
+
+    /// Replace the LLM provider (e.g., when a persona prefers another model).
+    ///
+    /// Usage and cost totals are kept; the token counter follows the new model.
+    pub fn set_provider(&mut self, provider: Arc<dyn LlmProvider>) {
+        self.token_counter = TokenCounter::for_model(provider.model_name());
+        self.provider = provider;
+    }
+
     /// Set knowledge addendum (distilled rules) to append to the system prompt.
     pub fn set_knowledge_addendum(&mut self, addendum: String) {
         self.knowledge_addendum = addendum;
     }
 
+    /// The knowledge addendum currently appended to the system prompt.
+    pub fn knowledge_addendum(&self) -> &str {
+        &self.knowledge_addendum
+    }
+
+    /// Set the pinned context section appended to the system prompt.
+    pub fn set_pinned_context(&mut self, section: String) {
+        self.pinned_context = section;
+    }
+
+    /// The pinned context section currently appended to the system prompt.
+    pub fn pinned_context(&self) -> &str {
+        &self.pinned_context
+    }
+
+    /// Swap the base system prompt, returning the previous one.
+    ///
+    /// Used by the fast path to run a task under a minimal prompt; the
+    /// caller restores the returned prompt afterwards.
+    pub fn replace_system_prompt(&mut self, prompt: impl Into<String>) -> String {
+        std::mem::replace(&mut self.system_prompt, prompt.into())
+    }
+
+    /// The token counter for the current model.
+    pub fn token_counter(&self) -> &TokenCounter {
+        &self.token_counter
+    }
+
+    /// Ask the provider for exact counts of the system prompt and tool
+    /// schemas, which are resent on every call. Counts are cached, so this
+    /// only reaches the provider when either has changed; providers without
+    /// a tokenizer endpoint leave the local estimate in place.
+    pub async fn calibrate_token_counts(&self, tools: Option<&[ToolDefinition]>) {
+        let system = self.build_messages(&[]);
+        let mut texts: Vec<String> = system
+            .iter()
+            .filter_map(|m| m.content.as_text().map(str::to_string))
+            .collect();
+        if let Some(tools) = tools
+            && !tools.is_empty()
+        {
+            texts.push(tool_schema_text(tools));
+        }
+        for text in texts {
+            if self.token_counter.has_provider_count(&text) {
+                continue;
+            }
+            match self.provider.count_tokens(&text).await {
+                Some(tokens) => self.token_counter.record(&text, tokens),
+                // Unsupported: don't ask again for the rest.
+                None => break,
+            }
+        }
+    }
+
     /// Estimate token count for messages using tiktoken-rs.
     pub fn estimate_tokens(&self, messages: &[Message]) -> usize {
         self.token_counter.count_messages(messages)
@@ -296,17 +690,27 @@ impl Brain {
 
     /// Construct messages for the LLM with system prompt prepended.
     ///
-    /// If a knowledge addendum has been set via `set_knowledge_addendum()`,
-    /// it is automatically appended to the system prompt.
+    /// The active persona fragment (see `set_persona_prompt()`), any knowledge
+    /// addendum set via `set_knowledge_addendum()` and the pinned context set via
+    /// `set_pinned_context()` are appended to the system prompt.
     ///
     /// After assembly, [`sanitize_tool_sequence`] runs to ensure tool_call→tool_result
     /// ordering is never broken regardless of compression, pinning, or system message injection.
     pub fn build_messages(&self, conversation: &[Message]) -> Vec<Message> {
         let mut messages = Vec::with_capacity(conversation.len() + 1);
-        if self.knowledge_addendum.is_empty() {
+        if self.knowledge_addendum.is_empty()
+            && self.persona_prompt.is_empty()
+            && self.pinned_context.is_empty()
+        {
             messages.push(Message::system(&self.system_prompt));
         } else {
-            let augmented = format!("{}{}", self.system_prompt, self.knowledge_addendum);
+            let augmented = format!(
+                "{}{}{}{}",
+                self.system_prompt,
+                self.persona_prompt,
+                self.knowledge_addendum,
+                self.pinned_context
+            );
             messages.push(Message::system(&augmented));
         }
         messages.extend_from_slice(conversation);
@@ -319,6 +723,19 @@ impl Brain {
         &mut self,
         conversation: &[Message],
         tools: Option<Vec<ToolDefinition>>,
+    ) -> Result<CompletionResponse, LlmError> {
+        self.think_with_policy(conversation, tools, CachePolicy::Full)
+            .await
+    }
+
+    /// Like [`think`](Self::think), with an explicit response cache policy.
+    ///
+    /// Cache hits skip the provider and report zero usage.
+    pub async fn think_with_policy(
+        &mut self,
+        conversation: &[Message],
+        tools: Option<Vec<ToolDefinition>>,
+        policy: CachePolicy,
     ) -> Result<CompletionResponse, LlmError> {
         let messages = self.build_messages(conversation);
         let mut token_estimate = self.provider.estimate_tokens(&messages);
@@ -349,16 +766,46 @@ impl Brain {
             model: None,
         };
 
-        let response = self.provider.complete(request).await?;
+        let cache = self.cache.clone().filter(|_| policy != CachePolicy::Bypass);
+        if let Some(cache) = &cache {
+            let model = self.provider.model_name();
+            let cached = cache.lookup(&request, model, policy);
+            let hit = cached.as_ref().and_then(|(r, _)| r.cache_hit);
+            let saved = cached.as_ref().map_or(0, |(_, saved)| *saved);
+            self.cache_task_stats.record(hit, saved);
+            crate::metrics::record_cache_lookup(model, hit);
+            if let Some((response, _)) = cached {
+                debug!(
+                    model,
+                    semantic = hit.is_some_and(|h| h.is_semantic()),
+                    "Response served from cache"
+                );
+                return Ok(response);
+            }
+        }
+        let cache_request = cache.as_ref().map(|_| request.clone());
+
+        let span = llm_request_span(self.provider.model_name());
+        let started = std::time::Instant::now();
+        let result = self
+            .provider
+            .complete(request)
+            .instrument(span.clone())
+            .await;
+        finish_llm_request(
+            &span,
+            self.provider.model_name(),
+            self.provider.cost_per_token(),
+            started,
+            &result,
+        );
+        let response = result?;
 
-        // Track usage
-        self.total_usage.accumulate(&response.usage);
-        let (input_rate, output_rate) = self.provider.cost_per_token();
-        let cost = CostEstimate {
-            input_cost: response.usage.input_tokens as f64 * input_rate,
-            output_cost: response.usage.output_tokens as f64 * output_rate,
-        };
-        self.total_cost.accumulate(&cost);
+        if let (Some(cache), Some(request)) = (&cache, &cache_request) {
+            cache.store(request, self.provider.model_name(), policy, &response);
+        }
+
+        let cost = self.account_usage(&response.usage);
 
         info!(
             input_tokens = response.usage.input_tokens,
@@ -380,11 +827,27 @@ impl Brain {
         conversation: &[Message],
         tools: Option<Vec<ToolDefinition>>,
         max_retries: usize,
+    ) -> Result<CompletionResponse, LlmError> {
+        self.think_with_retry_policy(conversation, tools, max_retries, CachePolicy::Full)
+            .await
+    }
+
+    /// Like [`think_with_retry`](Self::think_with_retry), with an explicit
+    /// response cache policy.
+    pub async fn think_with_retry_policy(
+        &mut self,
+        conversation: &[Message],
+        tools: Option<Vec<ToolDefinition>>,
+        max_retries: usize,
+        policy: CachePolicy,
     ) -> Result<CompletionResponse, LlmError> {
         let mut last_error = None;
 
         for attempt in 0..=max_retries {
-            match self.think(conversation, tools.clone()).await {
+            match self
+                .think_with_policy(conversation, tools.clone(), policy)
+                .await
+            {
                 Ok(response) => return Ok(response),
                 Err(e) if Self::is_retryable(&e) => {
                     if attempt < max_retries {
@@ -471,6 +934,14 @@ impl Brain {
         self.provider.cost_per_token()
     }
 
+    /// Estimated input tokens and cost of sending `handle` as an image to
+    /// the current provider.
+    pub fn image_cost(&self, handle: &AttachmentHandle) -> (usize, f64) {
+        let tokens = self.token_counter.image_tokens(handle);
+        let (input_rate, _) = self.provider.cost_per_token();
+        (tokens, tokens as f64 * input_rate)
+    }
+
     /// Get a reference to the underlying LLM provider.
     pub fn provider(&self) -> &dyn LlmProvider {
         &*self.provider
@@ -483,13 +954,49 @@ impl Brain {
 
     /// Track usage and cost from an external completion (e.g., streaming).
     pub fn track_usage(&mut self, usage: &TokenUsage) {
-        self.total_usage.accumulate(usage);
-        let (input_rate, output_rate) = self.provider.cost_per_token();
+        self.account_usage(usage);
+    }
+
+    /// Track usage of a completion made through another provider, such as
+    /// the vision fallback, at that provider's rates. Returns its cost.
+    pub fn track_usage_for(
+        &mut self,
+        provider: &dyn LlmProvider,
+        usage: &TokenUsage,
+    ) -> CostEstimate {
+        Self::accumulate_usage(&mut self.total_usage, &mut self.total_cost, provider, usage)
+    }
+
+    /// Add usage to the running totals and exported metrics, returning its cost.
+    fn account_usage(&mut self, usage: &TokenUsage) -> CostEstimate {
+        Self::accumulate_usage(
+            &mut self.total_usage,
+            &mut self.total_cost,
+            &*self.provider,
+            usage,
+        )
+    }
+
+    fn accumulate_usage(
+        total_usage: &mut TokenUsage,
+        total_cost: &mut CostEstimate,
+        provider: &dyn LlmProvider,
+        usage: &TokenUsage,
+    ) -> CostEstimate {
+        total_usage.accumulate(usage);
+        let (input_rate, output_rate) = provider.cost_per_token();
         let cost = CostEstimate {
             input_cost: usage.input_tokens as f64 * input_rate,
             output_cost: usage.output_tokens as f64 * output_rate,
         };
-        self.total_cost.accumulate(&cost);
+        total_cost.accumulate(&cost);
+        crate::metrics::record_token_usage(
+            provider.model_name(),
+            usage.input_tokens as u64,
+            usage.output_tokens as u64,
+            cost.total(),
+        );
+        cost
     }
 
     /// Get the current token usage as a fraction of the context window.
@@ -500,6 +1007,48 @@ impl Brain {
     }
 }
 
+/// Span covering one LLM completion request.
+pub(crate) fn llm_request_span(model: &str) -> tracing::Span {
+    tracing::info_span!(
+        "llm_request",
+        model,
+        input_tokens = tracing::field::Empty,
+        output_tokens = tracing::field::Empty,
+        outcome = tracing::field::Empty,
+    )
+}
+
+/// Record the result of an LLM request on its span, in exported metrics
+/// and in the event store. `rates` are the provider's per-token costs; a
+/// provider with no rates at all has unknown pricing.
+pub(crate) fn finish_llm_request(
+    span: &tracing::Span,
+    model: &str,
+    rates: (f64, f64),
+    started: std::time::Instant,
+    result: &Result<CompletionResponse, LlmError>,
+) {
+    let usage = result
+        .as_ref()
+        .map(|response| response.usage)
+        .unwrap_or_default();
+    if result.is_ok() {
+        span.record("input_tokens", usage.input_tokens);
+        span.record("output_tokens", usage.output_tokens);
+    }
+    span.record("outcome", if result.is_ok() { "success" } else { "error" });
+    crate::metrics::record_llm_request(model, result.is_ok(), started.elapsed());
+    crate::event_store::record(crate::event_store::Event::LlmRequest {
+        model: model.to_string(),
+        success: result.is_ok(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        input_tokens: usage.input_tokens as u64,
+        output_tokens: usage.output_tokens as u64,
+        cost_usd: (rates != (0.0, 0.0))
+            .then_some(usage.input_tokens as f64 * rates.0 + usage.output_tokens as f64 * rates.1),
+    });
+}
+
 /// A mock LLM provider for testing and development.
 pub struct MockLlmProvider {
     model: String,
@@ -542,6 +1091,7 @@ impl MockLlmProvider {
             },
             model: "mock-model".to_string(),
             finish_reason: Some("stop".to_string()),
+            cache_hit: None,
         }
     }
 
@@ -559,6 +1109,7 @@ impl MockLlmProvider {
             },
             model: "mock-model".to_string(),
             finish_reason: Some("tool_calls".to_string()),
+            cache_hit: None,
         }
     }
 
@@ -585,6 +1136,7 @@ impl MockLlmProvider {
             },
             model: "mock-model".to_string(),
             finish_reason: Some("tool_calls".to_string()),
+            cache_hit: None,
         }
     }
 }
@@ -642,6 +1194,7 @@ impl LlmProvider for MockLlmProvider {
                         _ => 50,
                     })
                     .sum(),
+                Content::Attachment { handle, .. } => handle.reference().len() / 4,
             })
             .sum::<usize>()
             + 100 // overhead for message structure
@@ -671,7 +1224,7 @@ CRITICAL — Tool selection rules:
 - You MUST use the dedicated tool for each task. Do NOT use shell_exec when a dedicated tool exists.
 - For clipboard: call macos_clipboard with {"action":"read"} or {"action":"write","content":"..."}
 - For battery/disk/CPU/version: call macos_system_info with {"action":"battery"}, {"action":"version"}, etc.
-- For running apps: call macos_app_control with {"action":"list_running"}
+- For running apps: call app_control with {"action":"list_running"}
 - For calendar: call macos_calendar. For reminders: call macos_reminders. For notes: call macos_notes.
 - For screenshots: call macos_screenshot. For Spotlight search: call macos_spotlight.
 - shell_exec is a last resort — only use it for commands that have no dedicated tool.
@@ -846,6 +1399,17 @@ impl TokenBudgetManager {
         self.halt_on_exceed
     }
 
+    /// The per-task cost limit (0.0 = unlimited) and whether exceeding it halts.
+    pub fn task_limit(&self) -> (f64, bool) {
+        (self.task_limit_usd, self.halt_on_exceed)
+    }
+
+    /// Replace the per-task cost limit, e.g. with a scheduled job's own budget.
+    pub fn set_task_limit(&mut self, limit_usd: f64, halt_on_exceed: bool) {
+        self.task_limit_usd = limit_usd;
+        self.halt_on_exceed = halt_on_exceed;
+    }
+
     /// Current session cost.
     pub fn session_cost(&self) -> f64 {
         self.session_cost
@@ -1169,6 +1733,119 @@ mod tests {
         assert!(count > 0); // Should use cl100k_base fallback
     }
 
+    const PINNED_O200K_PROSE: usize = 10;
+    const PINNED_O200K_CODE: usize = 36;
+    const PINNED_CL100K_PROSE: usize = 10;
+    const PINNED_CL100K_CODE: usize = 36;
+    const PINNED_PROSE: &str = "The quick brown fox jumps over the lazy dog.";
+    const PINNED_CODE: &str = "fn main() {\n    let v: Vec<u32> = (0..10).map(|x| x * x).collect();\n    println!(\"{:?}\", v);\n}";
+
+    #[test]
+    fn test_tokenizer_selection() {
+        assert_eq!(
+            TokenCounter::for_model("gpt-4o").tokenizer(),
+            TokenizerKind::Bpe("o200k_base")
+        );
+        assert_eq!(
+            TokenCounter::for_model("gpt-4").tokenizer(),
+            TokenizerKind::Bpe("cl100k_base")
+        );
+        assert_eq!(
+            TokenCounter::for_model("claude-sonnet-4-20250514").tokenizer(),
+            TokenizerKind::Approximate("claude")
+        );
+        assert_eq!(
+            TokenCounter::for_model("gemini-2.0-flash").tokenizer(),
+            TokenizerKind::Approximate("gemini")
+        );
+        assert_eq!(
+            TokenCounter::for_model("llama3.2").tokenizer(),
+            TokenizerKind::Approximate("local")
+        );
+        assert!(TokenCounter::heuristic("x").tokenizer().is_estimate());
+        assert!(!TokenizerKind::Bpe("o200k_base").is_estimate());
+    }
+
+    #[test]
+    fn test_pinned_token_counts() {
+        let o200k = TokenCounter::for_model("gpt-4o");
+        assert_eq!(o200k.count(PINNED_PROSE), PINNED_O200K_PROSE);
+        assert_eq!(o200k.count(PINNED_CODE), PINNED_O200K_CODE);
+
+        let cl100k = TokenCounter::for_model("gpt-4");
+        assert_eq!(cl100k.count(PINNED_PROSE), PINNED_CL100K_PROSE);
+        assert_eq!(cl100k.count(PINNED_CODE), PINNED_CL100K_CODE);
+
+        // Claude is approximated with cl100k_base and flagged as such.
+        let claude =
+            TokenCounter::for_model("claude-sonnet-4-20250514").count_detailed(PINNED_CODE);
+        assert_eq!(claude.tokens, PINNED_CL100K_CODE);
+        assert!(claude.tokenizer.is_estimate());
+
+        let heuristic = TokenCounter::heuristic("x").count_detailed(PINNED_PROSE);
+        assert_eq!(heuristic.tokens, 11);
+        assert_eq!(heuristic.tokenizer, TokenizerKind::Heuristic);
+    }
+
+    #[test]
+    fn test_image_pricing_per_provider() {
+        assert_eq!(ImagePricing::Anthropic.tokens(1000, 1000), 1334);
+        // Scaled to 1568 px on the long edge first.
+        assert_eq!(ImagePricing::Anthropic.tokens(3136, 3136), 3279);
+        // 1024² is scaled to 768², four 512 px tiles.
+        assert_eq!(ImagePricing::OpenAi.tokens(1024, 1024), 765);
+        assert_eq!(ImagePricing::Gemini.tokens(300, 300), 258);
+        assert_eq!(ImagePricing::Gemini.tokens(1000, 1000), 1032);
+
+        let handle = AttachmentHandle {
+            id: "att-0123456789ab".into(),
+            mime_type: "image/png".into(),
+            size: 1024,
+            description: "Mockup".into(),
+            thumbnail_text: None,
+            dimensions: Some((1000, 1000)),
+        };
+        let claude = TokenCounter::for_model("claude-sonnet-4-20250514");
+        assert_eq!(claude.image_tokens(&handle), 1334);
+        let resolved = Message::new(
+            Role::User,
+            Content::Attachment {
+                handle: handle.clone(),
+                image: Some(crate::types::ImageSource {
+                    media_type: "image/png".into(),
+                    data: String::new(),
+                }),
+            },
+        );
+        let unresolved = Message::new(Role::User, Content::attachment(handle));
+        assert_eq!(
+            claude.count_message(&resolved) - claude.count_message(&unresolved),
+            1334
+        );
+    }
+
+    #[test]
+    fn test_provider_counts_are_cached_per_model() {
+        let text = "x".repeat(TOKEN_CACHE_MIN_BYTES * 2);
+        let counter = TokenCounter::for_model("test-cache-model-a");
+        let local = counter.count_detailed(&text);
+        assert_eq!(local.tokenizer, TokenizerKind::Approximate("local"));
+        assert!(!counter.has_provider_count(&text));
+
+        counter.record(&text, 321);
+        assert!(counter.has_provider_count(&text));
+        assert_eq!(
+            counter.count_detailed(&text),
+            TokenCount {
+                tokens: 321,
+                tokenizer: TokenizerKind::Provider
+            }
+        );
+        // Another model keeps its own count.
+        let other = TokenCounter::for_model("test-cache-model-b");
+        assert_eq!(other.count(&text), local.tokens);
+    }
+
     #[test]
     fn test_brain_estimate_tokens() {
         let provider = Arc::new(MockLlmProvider::new());
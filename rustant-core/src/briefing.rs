@@ -0,0 +1,1355 @@
+//! Daily briefing engine: assembles pluggable sections into a markdown briefing.
+//!
+//! A [`BriefingEngine`] holds registered [`BriefingSection`]s. Core ships the
+//! `activity` (agent sessions), `inbox` (channel digests), `people`
+//! (relationship reminders) and `system` sections;
+//! `rustant-tools` adds calendar, flashcards, deadlines and finance sections.
+//! A [`BriefingConfig`] picks which sections run, in what order and with which
+//! options:
+//!
+//! ```toml
+//! [briefing]
+//! schedule = "0 30 7 * * MON-FRI"
+//! week_ahead_schedule = "0 0 18 * * SUN"
+//! max_tokens = 1200
+//! delivery = { type = "channel", channel = "slack", destination_id = "D0123" }
+//!
+//! [[briefing.sections]]
+//! id = "calendar"
+//!
+//! [[briefing.sections]]
+//! id = "finance"
+//! enabled = false
+//! ```
+//!
+//! Listed sections run first, in the listed order; registered sections that are
+//! not listed follow in registration order when they are enabled by default.
+//! Sections run concurrently and independently: one that fails or times out is
+//! rendered as a one-line "unavailable" note instead of aborting the briefing.
+//! When the rendered briefing exceeds `max_tokens`, the largest sections are
+//! condensed one at a time — by the LLM when a provider is supplied, otherwise
+//! by keeping their first lines — until it fits.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::brain::{LlmProvider, TokenCounter};
+use crate::canvas::{CanvasError, CanvasManager, CanvasTarget, ContentType};
+use crate::channels::{ChannelManager, ChannelMessage, ChannelType, ChannelUser};
+use crate::people::{PersonGraph, SuggestionConfig};
+use crate::scheduler::{CronJob, CronJobConfig};
+use crate::session_manager::SessionManager;
+use crate::types::{Artifact, CompletionRequest, Content, Message};
+
+/// Cron job task string that generates the daily briefing.
+pub const BRIEFING_TASK: &str = "rustant:briefing";
+/// Cron job task string that generates the week-ahead briefing.
+pub const WEEK_AHEAD_TASK: &str = "rustant:briefing --week-ahead";
+
+/// Smallest size a section is condensed to when enforcing the token budget.
+const MIN_SECTION_TOKENS: usize = 40;
+/// Model used for token counting when no provider is supplied.
+const DEFAULT_COUNTING_MODEL: &str = "gpt-4o";
+
+/// Errors from validating or delivering a briefing.
+#[derive(Debug, thiserror::Error)]
+pub enum BriefingError {
+    #[error("Unknown briefing section '{id}' (available: {available})")]
+    UnknownSection { id: String, available: String },
+    #[error("Briefing section '{id}' is configured more than once")]
+    DuplicateSection { id: String },
+    #[error("Invalid briefing {field}: {message}")]
+    InvalidConfig { field: String, message: String },
+    #[error("Briefing delivery failed: {message}")]
+    Delivery { message: String },
+}
+
+/// The span of time a briefing covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BriefingPeriod {
+    /// Today, looking back over the last day.
+    Day,
+    /// The next 7 days, looking back over the last week.
+    WeekAhead,
+}
+
+impl BriefingPeriod {
+    /// Number of days covered (and looked back over).
+    pub fn days(self) -> i64 {
+        match self {
+            Self::Day => 1,
+            Self::WeekAhead => 7,
+        }
+    }
+
+    /// Heading used for the briefing.
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Day => "Daily Briefing",
+            Self::WeekAhead => "Week Ahead",
+        }
+    }
+
+    /// Recognize a cron task string produced by [`BriefingConfig::cron_jobs`].
+    pub fn from_task(task: &str) -> Option<Self> {
+        match task.trim() {
+            BRIEFING_TASK => Some(Self::Day),
+            WEEK_AHEAD_TASK => Some(Self::WeekAhead),
+            _ => None,
+        }
+    }
+}
+
+/// Per-section configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BriefingSectionConfig {
+    /// Section identifier (e.g. "calendar", "inbox").
+    pub id: String,
+    /// Whether the section is included (default: true).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Heading override.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Section-specific options (e.g. `limit`, `disk_warn_percent`).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub options: HashMap<String, serde_json::Value>,
+}
+
+impl BriefingSectionConfig {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            enabled: true,
+            title: None,
+            options: HashMap::new(),
+        }
+    }
+}
+
+/// Where a generated briefing is sent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BriefingDelivery {
+    /// Only print / return the markdown.
+    #[default]
+    None,
+    /// Push to a canvas target (broadcast when unset).
+    Canvas {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        target: Option<String>,
+    },
+    /// Send to a configured channel.
+    Channel {
+        /// Channel name as registered in `[channels]` (e.g. "slack").
+        channel: String,
+        /// Conversation/chat ID on that channel.
+        #[serde(default)]
+        destination_id: String,
+    },
+}
+
+/// Briefing configuration (`[briefing]` in config.toml).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BriefingConfig {
+    /// Section order and settings; unlisted sections follow in default order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<BriefingSectionConfig>,
+    /// Cron expression for the daily briefing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// Cron expression for the week-ahead briefing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub week_ahead_schedule: Option<String>,
+    /// IANA timezone used for "today" and the schedules (default: local time).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Where scheduled briefings are delivered.
+    #[serde(default)]
+    pub delivery: BriefingDelivery,
+    /// Token budget for the rendered briefing.
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: usize,
+    /// Per-section time limit in seconds.
+    #[serde(default = "default_section_timeout_secs")]
+    pub section_timeout_secs: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_tokens() -> usize {
+    1500
+}
+
+fn default_section_timeout_secs() -> u64 {
+    20
+}
+
+impl Default for BriefingConfig {
+    fn default() -> Self {
+        Self {
+            sections: Vec::new(),
+            schedule: None,
+            week_ahead_schedule: None,
+            timezone: None,
+            delivery: BriefingDelivery::None,
+            max_tokens: default_max_tokens(),
+            section_timeout_secs: default_section_timeout_secs(),
+        }
+    }
+}
+
+impl BriefingConfig {
+    /// Cron jobs for the configured schedules, named `briefing` and
+    /// `briefing-week-ahead`.
+    pub fn cron_jobs(&self) -> Vec<CronJobConfig> {
+        let mut jobs = Vec::new();
+        for (name, schedule, task) in [
+            ("briefing", &self.schedule, BRIEFING_TASK),
+            (
+                "briefing-week-ahead",
+                &self.week_ahead_schedule,
+                WEEK_AHEAD_TASK,
+            ),
+        ] {
+            if let Some(schedule) = schedule {
+                let mut job = CronJobConfig::new(name, schedule, task);
+                job.timezone = self.timezone.clone();
+                jobs.push(job);
+            }
+        }
+        jobs
+    }
+
+    fn parse_timezone(&self) -> Result<Option<chrono_tz::Tz>, BriefingError> {
+        self.timezone
+            .as_deref()
+            .map(|tz| {
+                tz.parse::<chrono_tz::Tz>()
+                    .map_err(|e| BriefingError::InvalidConfig {
+                        field: "timezone".into(),
+                        message: e.to_string(),
+                    })
+            })
+            .transpose()
+    }
+
+    /// The calendar date of `now` in the configured timezone.
+    pub fn today(&self, now: DateTime<Utc>) -> Result<NaiveDate, BriefingError> {
+        Ok(match self.parse_timezone()? {
+            Some(tz) => now.with_timezone(&tz).date_naive(),
+            None => now.with_timezone(&chrono::Local).date_naive(),
+        })
+    }
+}
+
+/// Inputs available to a section while rendering.
+#[derive(Debug, Clone)]
+pub struct BriefingContext {
+    pub period: BriefingPeriod,
+    pub now: DateTime<Utc>,
+    /// Today's date in the briefing timezone.
+    pub today: NaiveDate,
+    pub workspace: PathBuf,
+    /// The section's `options` table from config.
+    pub options: HashMap<String, serde_json::Value>,
+}
+
+impl BriefingContext {
+    pub fn new(period: BriefingPeriod, now: DateTime<Utc>, workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            period,
+            now,
+            today: now.date_naive(),
+            workspace: workspace.into(),
+            options: HashMap::new(),
+        }
+    }
+
+    /// Last date covered, inclusive: today, or six days out for the week ahead.
+    pub fn last_day(&self) -> NaiveDate {
+        self.today + Duration::days(self.period.days() - 1)
+    }
+
+    /// End of the forward-looking window.
+    pub fn until(&self) -> DateTime<Utc> {
+        self.now + Duration::days(self.period.days())
+    }
+
+    /// Start of the look-back window used for activity reports.
+    pub fn since(&self) -> DateTime<Utc> {
+        self.now - Duration::days(self.period.days())
+    }
+
+    /// "day" or "week", for "in the last …" phrasing.
+    pub fn lookback_label(&self) -> &'static str {
+        match self.period {
+            BriefingPeriod::Day => "day",
+            BriefingPeriod::WeekAhead => "week",
+        }
+    }
+
+    pub fn option_u64(&self, key: &str) -> Option<u64> {
+        self.options.get(key).and_then(|v| v.as_u64())
+    }
+
+    pub fn option_str(&self, key: &str) -> Option<&str> {
+        self.options.get(key).and_then(|v| v.as_str())
+    }
+}
+
+/// A pluggable part of the briefing.
+#[async_trait]
+pub trait BriefingSection: Send + Sync {
+    /// Stable identifier used in config.
+    fn id(&self) -> &str;
+
+    /// Default heading.
+    fn title(&self) -> &str;
+
+    /// Whether the section runs when config does not mention it.
+    fn enabled_by_default(&self) -> bool {
+        true
+    }
+
+    /// Render the section body as markdown. An empty body omits the section;
+    /// an error renders as a one-line "unavailable" note.
+    async fn render(&self, ctx: &BriefingContext) -> Result<String, String>;
+}
+
+/// Outcome of rendering one section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionStatus {
+    Ok,
+    /// Condensed to fit the token budget.
+    Summarized,
+    /// Failed or timed out; carries the reason.
+    Unavailable(String),
+}
+
+/// A rendered section.
+#[derive(Debug, Clone)]
+pub struct BriefingSectionOutput {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub status: SectionStatus,
+}
+
+impl BriefingSectionOutput {
+    fn to_markdown(&self) -> String {
+        match &self.status {
+            SectionStatus::Unavailable(reason) => {
+                format!("_{} unavailable: {}_\n\n", self.title, reason)
+            }
+            _ => format!("## {}\n\n{}\n\n", self.title, self.body.trim_end()),
+        }
+    }
+}
+
+/// A generated briefing.
+#[derive(Debug, Clone)]
+pub struct Briefing {
+    pub period: BriefingPeriod,
+    pub generated_at: DateTime<Utc>,
+    pub first_day: NaiveDate,
+    pub last_day: NaiveDate,
+    pub sections: Vec<BriefingSectionOutput>,
+    /// Token count of the rendered markdown.
+    pub tokens: usize,
+}
+
+/// Where [`Briefing::deliver`] sent a briefing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BriefingDelivered {
+    /// Delivery is not configured.
+    Nowhere,
+    /// Pushed to the canvas.
+    Canvas { id: uuid::Uuid, target: String },
+    /// Queued on a channel; flush the manager to send it.
+    Queued { channel: String },
+}
+
+impl Briefing {
+    /// Heading, e.g. "Daily Briefing — 2026-03-02".
+    pub fn title(&self) -> String {
+        if self.first_day == self.last_day {
+            format!("{} — {}", self.period.title(), self.first_day)
+        } else {
+            format!(
+                "{} — {} to {}",
+                self.period.title(),
+                self.first_day,
+                self.last_day
+            )
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# {}\n\n", self.title());
+        if self.sections.is_empty() {
+            md.push_str("_Nothing to report._\n");
+        }
+        for section in &self.sections {
+            md.push_str(&section.to_markdown());
+        }
+        md.truncate(md.trim_end().len());
+        md.push('\n');
+        md
+    }
+
+    /// Sections that failed, as `(id, reason)`.
+    pub fn unavailable(&self) -> Vec<(&str, &str)> {
+        self.sections
+            .iter()
+            .filter_map(|s| match &s.status {
+                SectionStatus::Unavailable(reason) => Some((s.id.as_str(), reason.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The briefing as a markdown canvas artifact for `target`.
+    pub fn canvas_artifact(&self, id: uuid::Uuid, target: &str) -> Artifact {
+        Artifact::CanvasItem {
+            id: id.to_string(),
+            target: target.to_string(),
+            content_type: "markdown".into(),
+            content: self.to_markdown(),
+        }
+    }
+
+    /// Push the markdown to a canvas target.
+    pub fn push_to_canvas(
+        &self,
+        canvas: &mut CanvasManager,
+        target: &CanvasTarget,
+    ) -> Result<uuid::Uuid, CanvasError> {
+        canvas.push(target, ContentType::Markdown, self.to_markdown())
+    }
+
+    /// The briefing as an outgoing channel message.
+    pub fn to_channel_message(
+        &self,
+        channel_type: ChannelType,
+        destination_id: &str,
+    ) -> ChannelMessage {
+        let sender = ChannelUser::new("rustant", channel_type).with_name("Rustant");
+        ChannelMessage::text(channel_type, destination_id, sender, self.to_markdown())
+            .with_metadata("briefing_period", format!("{:?}", self.period))
+    }
+
+    /// Send the briefing as configured. Channel messages are queued on
+    /// `manager`; the caller flushes it.
+    pub fn deliver(
+        &self,
+        delivery: &BriefingDelivery,
+        manager: &mut ChannelManager,
+        canvas: &mut CanvasManager,
+    ) -> Result<BriefingDelivered, BriefingError> {
+        match delivery {
+            BriefingDelivery::None => Ok(BriefingDelivered::Nowhere),
+            BriefingDelivery::Canvas { target } => {
+                let canvas_target = target
+                    .clone()
+                    .map(CanvasTarget::Named)
+                    .unwrap_or(CanvasTarget::Broadcast);
+                let id = self.push_to_canvas(canvas, &canvas_target).map_err(|e| {
+                    BriefingError::Delivery {
+                        message: e.to_string(),
+                    }
+                })?;
+                Ok(BriefingDelivered::Canvas {
+                    id,
+                    target: target.clone().unwrap_or_else(|| "broadcast".into()),
+                })
+            }
+            BriefingDelivery::Channel {
+                channel,
+                destination_id,
+            } => {
+                let channel_type =
+                    manager
+                        .channel_type(channel)
+                        .ok_or_else(|| BriefingError::Delivery {
+                            message: format!("channel '{}' is not registered", channel),
+                        })?;
+                manager.enqueue(
+                    channel.clone(),
+                    self.to_channel_message(channel_type, destination_id),
+                );
+                Ok(BriefingDelivered::Queued {
+                    channel: channel.clone(),
+                })
+            }
+        }
+    }
+}
+
+/// A section scheduled to run, with its effective config.
+type PlannedSection = (Arc<dyn BriefingSection>, BriefingSectionConfig);
+
+/// Registry of briefing sections and the generator that runs them.
+pub struct BriefingEngine {
+    workspace: PathBuf,
+    sections: Vec<Arc<dyn BriefingSection>>,
+}
+
+impl BriefingEngine {
+    /// An engine with no sections.
+    pub fn new(workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace: workspace.into(),
+            sections: Vec::new(),
+        }
+    }
+
+    /// An engine with the core `activity`, `inbox`, `people` and `system` sections.
+    pub fn with_core_sections(
+        workspace: impl Into<PathBuf>,
+        config: &crate::config::AgentConfig,
+    ) -> Self {
+        let mut engine = Self::new(workspace);
+        let inbox = InboxSection::from_config(&engine.workspace, config);
+        engine.register(Arc::new(ActivitySection));
+        engine.register(Arc::new(inbox));
+        engine.register(Arc::new(PeopleSection));
+        engine.register(Arc::new(SystemSection));
+        engine
+    }
+
+    /// Add a section; one with the same id is replaced in place.
+    pub fn register(&mut self, section: Arc<dyn BriefingSection>) {
+        match self.sections.iter_mut().find(|s| s.id() == section.id()) {
+            Some(existing) => *existing = section,
+            None => self.sections.push(section),
+        }
+    }
+
+    /// Registered section ids, in registration order.
+    pub fn section_ids(&self) -> Vec<&str> {
+        self.sections.iter().map(|s| s.id()).collect()
+    }
+
+    /// Check section ids, schedules, timezone and delivery settings.
+    pub fn validate(&self, config: &BriefingConfig) -> Result<(), BriefingError> {
+        self.plan(config)?;
+        config.parse_timezone()?;
+        for job in config.cron_jobs() {
+            CronJob::new(job).map_err(|e| BriefingError::InvalidConfig {
+                field: "schedule".into(),
+                message: e.to_string(),
+            })?;
+        }
+        if let BriefingDelivery::Channel { channel, .. } = &config.delivery
+            && channel.trim().is_empty()
+        {
+            return Err(BriefingError::InvalidConfig {
+                field: "delivery".into(),
+                message: "channel delivery needs a channel name".into(),
+            });
+        }
+        if config.max_tokens < MIN_SECTION_TOKENS {
+            return Err(BriefingError::InvalidConfig {
+                field: "max_tokens".into(),
+                message: format!("must be at least {}", MIN_SECTION_TOKENS),
+            });
+        }
+        Ok(())
+    }
+
+    /// Sections to run, in order, with their config.
+    fn plan(&self, config: &BriefingConfig) -> Result<Vec<PlannedSection>, BriefingError> {
+        let mut seen = HashSet::new();
+        let mut planned = Vec::new();
+        for entry in &config.sections {
+            if !seen.insert(entry.id.as_str()) {
+                return Err(BriefingError::DuplicateSection {
+                    id: entry.id.clone(),
+                });
+            }
+            let section = self
+                .sections
+                .iter()
+                .find(|s| s.id() == entry.id)
+                .ok_or_else(|| BriefingError::UnknownSection {
+                    id: entry.id.clone(),
+                    available: self.section_ids().join(", "),
+                })?;
+            if entry.enabled {
+                planned.push((section.clone(), entry.clone()));
+            }
+        }
+        for section in &self.sections {
+            if !seen.contains(section.id()) && section.enabled_by_default() {
+                planned.push((section.clone(), BriefingSectionConfig::new(section.id())));
+            }
+        }
+        Ok(planned)
+    }
+
+    /// Generate a briefing. `provider`, when given, condenses sections that
+    /// push the briefing over its token budget.
+    pub async fn generate(
+        &self,
+        config: &BriefingConfig,
+        period: BriefingPeriod,
+        now: DateTime<Utc>,
+        provider: Option<Arc<dyn LlmProvider>>,
+    ) -> Result<Briefing, BriefingError> {
+        self.validate(config)?;
+        let today = config.today(now)?;
+        let timeout = std::time::Duration::from_secs(config.section_timeout_secs.max(1));
+
+        let renders = self.plan(config)?.into_iter().map(|(section, entry)| {
+            let ctx = BriefingContext {
+                period,
+                now,
+                today,
+                workspace: self.workspace.clone(),
+                options: entry.options,
+            };
+            let title = entry.title.unwrap_or_else(|| section.title().to_string());
+            async move {
+                let result = match tokio::time::timeout(timeout, section.render(&ctx)).await {
+                    Ok(result) => result,
+                    Err(_) => Err(format!("timed out after {}s", timeout.as_secs())),
+                };
+                let (body, status) = match result {
+                    Ok(body) => (body, SectionStatus::Ok),
+                    Err(reason) => {
+                        tracing::warn!(section = section.id(), %reason, "Briefing section failed");
+                        (String::new(), SectionStatus::Unavailable(one_line(&reason)))
+                    }
+                };
+                BriefingSectionOutput {
+                    id: section.id().to_string(),
+                    title,
+                    body,
+                    status,
+                }
+            }
+        });
+        let sections = futures::future::join_all(renders)
+            .await
+            .into_iter()
+            .filter(|s| s.status != SectionStatus::Ok || !s.body.trim().is_empty())
+            .collect();
+
+        let mut briefing = Briefing {
+            period,
+            generated_at: now,
+            first_day: today,
+            last_day: today + Duration::days(period.days() - 1),
+            sections,
+            tokens: 0,
+        };
+        let counter = TokenCounter::for_model(
+            provider
+                .as_ref()
+                .map(|p| p.model_name())
+                .unwrap_or(DEFAULT_COUNTING_MODEL),
+        );
+        fit_to_budget(&mut briefing, config.max_tokens, &counter, provider).await;
+        briefing.tokens = counter.count(&briefing.to_markdown());
+        Ok(briefing)
+    }
+}
+
+/// Condense the largest sections until the briefing fits `max_tokens`.
+async fn fit_to_budget(
+    briefing: &mut Briefing,
+    max_tokens: usize,
+    counter: &TokenCounter,
+    provider: Option<Arc<dyn LlmProvider>>,
+) {
+    loop {
+        let total = counter.count(&briefing.to_markdown());
+        if total <= max_tokens {
+            return;
+        }
+        let Some((index, size)) = briefing
+            .sections
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.status == SectionStatus::Ok)
+            .map(|(i, s)| (i, counter.count(&s.body)))
+            .filter(|(_, size)| *size > MIN_SECTION_TOKENS)
+            .max_by_key(|(i, size)| (*size, std::cmp::Reverse(*i)))
+        else {
+            return;
+        };
+        let target = size
+            .saturating_sub(total - max_tokens)
+            .max(MIN_SECTION_TOKENS);
+        let section = &mut briefing.sections[index];
+        let mut body = None;
+        if let Some(provider) = &provider {
+            match summarize_section(provider.as_ref(), &section.title, &section.body, target).await
+            {
+                Ok(summary) => body = Some(summary),
+                Err(e) => {
+                    tracing::debug!(section = %section.id, error = %e, "Falling back to truncation")
+                }
+            }
+        }
+        let body = body.unwrap_or_else(|| section.body.clone());
+        section.body = if counter.count(&body) > target {
+            truncate_to_tokens(&body, target, counter)
+        } else {
+            body
+        };
+        section.status = SectionStatus::Summarized;
+    }
+}
+
+async fn summarize_section(
+    provider: &dyn LlmProvider,
+    title: &str,
+    body: &str,
+    target_tokens: usize,
+) -> Result<String, String> {
+    let prompt = format!(
+        "Condense this section of a personal briefing to at most {} tokens. \
+         Keep dates, names and numbers; drop detail. Reply with markdown bullet \
+         points only.\n\n## {}\n\n{}",
+        target_tokens, title, body
+    );
+    let request = CompletionRequest {
+        messages: vec![Message::user(prompt)],
+        tools: None,
+        temperature: 0.2,
+        max_tokens: Some(target_tokens),
+        stop_sequences: Vec::new(),
+        model: None,
+    };
+    let response = provider
+        .complete(request)
+        .await
+        .map_err(|e| e.to_string())?;
+    match response.message.content {
+        Content::Text { text } if !text.trim().is_empty() => Ok(text.trim().to_string()),
+        _ => Err("empty summary".into()),
+    }
+}
+
+/// Keep whole leading lines of `body` within `target` tokens, noting how many
+/// lines were dropped.
+fn truncate_to_tokens(body: &str, target: usize, counter: &TokenCounter) -> String {
+    let lines: Vec<&str> = body.lines().filter(|l| !l.trim().is_empty()).collect();
+    let mut kept = Vec::new();
+    for line in &lines {
+        let dropped = lines.len() - kept.len() - 1;
+        let mut candidate = kept.join("\n");
+        if !candidate.is_empty() {
+            candidate.push('\n');
+        }
+        candidate.push_str(line);
+        if dropped > 0 {
+            candidate.push_str(&format!("\n- …and {} more", dropped));
+        }
+        if !kept.is_empty() && counter.count(&candidate) > target {
+            break;
+        }
+        kept.push(*line);
+    }
+    let dropped = lines.len() - kept.len();
+    let mut out = kept.join("\n");
+    if dropped > 0 {
+        out.push_str(&format!("\n- …and {} more", dropped));
+    }
+    out
+}
+
+/// First line of an error, capped for the "unavailable" note.
+fn one_line(reason: &str) -> String {
+    let line = reason
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("failed");
+    let line = line.trim();
+    if line.chars().count() > 120 {
+        format!("{}…", line.chars().take(119).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Core sections
+// ---------------------------------------------------------------------------
+
+/// Agent sessions active in the look-back window.
+pub struct ActivitySection;
+
+#[async_trait]
+impl BriefingSection for ActivitySection {
+    fn id(&self) -> &str {
+        "activity"
+    }
+
+    fn title(&self) -> &str {
+        "Agent Activity"
+    }
+
+    async fn render(&self, ctx: &BriefingContext) -> Result<String, String> {
+        let index = SessionManager::read_index(&ctx.workspace).map_err(|e| e.to_string())?;
+        let mut recent: Vec<_> = index
+            .entries
+            .iter()
+            .filter(|e| e.updated_at >= ctx.since())
+            .collect();
+        if recent.is_empty() {
+            return Ok(format!(
+                "No agent sessions in the last {}.",
+                ctx.lookback_label()
+            ));
+        }
+        recent.sort_by_key(|e| std::cmp::Reverse(e.updated_at));
+        let tokens: usize = recent.iter().map(|e| e.total_tokens).sum();
+        let completed = recent.iter().filter(|e| e.completed).count();
+        let mut out = format!(
+            "{} session(s), {} completed, {} tokens used.\n",
+            recent.len(),
+            completed,
+            tokens
+        );
+        let limit = ctx.option_u64("limit").unwrap_or(8) as usize;
+        for entry in recent.iter().take(limit) {
+            let what = entry
+                .summary
+                .as_deref()
+                .or(entry.last_goal.as_deref())
+                .unwrap_or("no goal recorded");
+            out.push_str(&format!(
+                "- **{}** — {} ({}, {} messages)\n",
+                entry.name,
+                what.lines().next().unwrap_or_default(),
+                if entry.completed {
+                    "done"
+                } else {
+                    "in progress"
+                },
+                entry.message_count
+            ));
+        }
+        if recent.len() > limit {
+            out.push_str(&format!("- …and {} more\n", recent.len() - limit));
+        }
+        Ok(out)
+    }
+}
+
+/// Channel digests exported in the look-back window.
+pub struct InboxSection {
+    digest_dir: PathBuf,
+}
+
+impl InboxSection {
+    pub fn new(digest_dir: PathBuf) -> Self {
+        Self { digest_dir }
+    }
+
+    /// Read digests from the configured `digest_dir`, relative to `workspace`.
+    pub fn from_config(workspace: &Path, config: &crate::config::AgentConfig) -> Self {
+        let digest_dir = config
+            .intelligence
+            .as_ref()
+            .map(|i| i.digest_dir.clone())
+            .unwrap_or_else(|| crate::config::IntelligenceConfig::default().digest_dir);
+        Self::new(workspace.join(digest_dir))
+    }
+}
+
+#[async_trait]
+impl BriefingSection for InboxSection {
+    fn id(&self) -> &str {
+        "inbox"
+    }
+
+    fn title(&self) -> &str {
+        "Inbox"
+    }
+
+    async fn render(&self, ctx: &BriefingContext) -> Result<String, String> {
+        let mut digests = Vec::new();
+        if self.digest_dir.exists() {
+            let entries = std::fs::read_dir(&self.digest_dir)
+                .map_err(|e| format!("cannot read {}: {}", self.digest_dir.display(), e))?;
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let Some(at) = name
+                    .strip_prefix("digest_")
+                    .and_then(|n| n.strip_suffix(".md"))
+                    .and_then(|n| chrono::NaiveDateTime::parse_from_str(n, "%Y-%m-%d_%H%M").ok())
+                else {
+                    continue;
+                };
+                if at.and_utc() >= ctx.since() {
+                    digests.push((at, entry.path()));
+                }
+            }
+        }
+        if digests.is_empty() {
+            return Ok(format!(
+                "No channel digests in the last {}.",
+                ctx.lookback_label()
+            ));
+        }
+        digests.sort();
+
+        let mut out = String::new();
+        let mut items = Vec::new();
+        for (at, path) in &digests {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+            let (summary, open) = parse_digest(&text);
+            out.push_str(&format!(
+                "- {}: {}\n",
+                at.format("%a %H:%M"),
+                summary.unwrap_or("no summary")
+            ));
+            for item in open {
+                if !items.contains(&item) {
+                    items.push(item);
+                }
+            }
+        }
+        if !items.is_empty() {
+            out.push_str("\n**Open action items:**\n");
+            for item in &items {
+                out.push_str(&format!("- {}\n", item));
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// The summary line and unscheduled action items of an exported digest.
+fn parse_digest(text: &str) -> (Option<&str>, Vec<String>) {
+    let mut heading = "";
+    let mut summary = None;
+    let mut items = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(h) = line.strip_prefix("## ") {
+            heading = h;
+            continue;
+        }
+        match heading {
+            "Summary" if summary.is_none() && !line.is_empty() => summary = Some(line),
+            "Action Items" => {
+                if let Some(item) = line.strip_prefix("- [ ] ") {
+                    items.push(item.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    (summary, items)
+}
+
+/// Relationship-maintenance suggestions from the person graph.
+///
+/// Options: `reply_after_days` (default 3), `stale_after_days` (default 30)
+/// and `limit` (default 5).
+pub struct PeopleSection;
+
+#[async_trait]
+impl BriefingSection for PeopleSection {
+    fn id(&self) -> &str {
+        "people"
+    }
+
+    fn title(&self) -> &str {
+        "People"
+    }
+
+    async fn render(&self, ctx: &BriefingContext) -> Result<String, String> {
+        let graph = PersonGraph::load(&ctx.workspace);
+        let defaults = SuggestionConfig::default();
+        let config = SuggestionConfig {
+            reply_after_days: ctx
+                .option_u64("reply_after_days")
+                .map_or(defaults.reply_after_days, |d| d as i64),
+            stale_after_days: ctx
+                .option_u64("stale_after_days")
+                .map_or(defaults.stale_after_days, |d| d as i64),
+            ..defaults
+        };
+        let suggestions = graph.suggestions(ctx.now, &config);
+        let limit = ctx.option_u64("limit").unwrap_or(5) as usize;
+        let mut out = String::new();
+        for suggestion in suggestions.iter().take(limit) {
+            out.push_str(&format!("- {}\n", suggestion.message));
+        }
+        if suggestions.len() > limit {
+            out.push_str(&format!("- …and {} more\n", suggestions.len() - limit));
+        }
+        Ok(out)
+    }
+}
+
+/// Disk usage and interrupted sessions.
+pub struct SystemSection;
+
+#[async_trait]
+impl BriefingSection for SystemSection {
+    fn id(&self) -> &str {
+        "system"
+    }
+
+    fn title(&self) -> &str {
+        "System Alerts"
+    }
+
+    async fn render(&self, ctx: &BriefingContext) -> Result<String, String> {
+        let mut alerts = Vec::new();
+
+        #[cfg(unix)]
+        {
+            let threshold = ctx.option_u64("disk_warn_percent").unwrap_or(90);
+            let output = tokio::process::Command::new("df")
+                .arg("-Pk")
+                .arg(&ctx.workspace)
+                .output()
+                .await
+                .map_err(|e| format!("df failed: {}", e))?;
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if let Some((used, mount)) = parse_df(&stdout)
+                && used >= threshold
+            {
+                alerts.push(format!("- Disk {}% full on {}", used, mount));
+            }
+        }
+
+        let index = SessionManager::read_index(&ctx.workspace).map_err(|e| e.to_string())?;
+        let interrupted: Vec<&str> = index
+            .entries
+            .iter()
+            .filter(|e| !e.completed && e.updated_at >= ctx.since())
+            .map(|e| e.name.as_str())
+            .collect();
+        if !interrupted.is_empty() {
+            alerts.push(format!(
+                "- {} unfinished session(s): {}",
+                interrupted.len(),
+                interrupted.join(", ")
+            ));
+        }
+
+        if alerts.is_empty() {
+            Ok("No alerts.".into())
+        } else {
+            Ok(alerts.join("\n"))
+        }
+    }
+}
+
+/// Capacity percentage and mount point from `df -P` output.
+#[cfg(unix)]
+fn parse_df(output: &str) -> Option<(u64, String)> {
+    let fields: Vec<&str> = output.lines().nth(1)?.split_whitespace().collect();
+    let used = fields.get(4)?.trim_end_matches('%').parse().ok()?;
+    Some((used, fields.get(5)?.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brain::MockLlmProvider;
+
+    struct StaticSection {
+        id: &'static str,
+        body: Result<String, String>,
+        default: bool,
+    }
+
+    impl StaticSection {
+        fn ok(id: &'static str, body: &str) -> Arc<Self> {
+            Arc::new(Self {
+                id,
+                body: Ok(body.into()),
+                default: true,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl BriefingSection for StaticSection {
+        fn id(&self) -> &str {
+            self.id
+        }
+        fn title(&self) -> &str {
+            self.id
+        }
+        fn enabled_by_default(&self) -> bool {
+            self.default
+        }
+        async fn render(&self, ctx: &BriefingContext) -> Result<String, String> {
+            self.body
+                .clone()
+                .map(|b| format!("{} ({})", b, ctx.last_day()))
+        }
+    }
+
+    fn engine() -> BriefingEngine {
+        let mut engine = BriefingEngine::new(std::env::temp_dir());
+        engine.register(StaticSection::ok("calendar", "3 events"));
+        engine.register(Arc::new(StaticSection {
+            id: "finance",
+            body: Err("permission denied\nstack trace".into()),
+            default: true,
+        }));
+        engine.register(Arc::new(StaticSection {
+            id: "weather",
+            body: Ok("sunny".into()),
+            default: false,
+        }));
+        engine
+    }
+
+    fn config() -> BriefingConfig {
+        BriefingConfig {
+            timezone: Some("UTC".into()),
+            ..Default::default()
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2026-03-02T08:00:00Z".parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_failed_section_renders_unavailable_note() {
+        let briefing = engine()
+            .generate(&config(), BriefingPeriod::Day, now(), None)
+            .await
+            .unwrap();
+        let md = briefing.to_markdown();
+        assert!(md.starts_with("# Daily Briefing — 2026-03-02\n"));
+        assert!(md.contains("## calendar\n\n3 events (2026-03-02)"));
+        assert!(md.contains("_finance unavailable: permission denied_"));
+        assert!(!md.contains("stack trace"));
+        assert!(!md.contains("sunny"), "weather is opt-in");
+        assert_eq!(
+            briefing.unavailable(),
+            vec![("finance", "permission denied")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_config_orders_enables_and_retitles_sections() {
+        let mut config = config();
+        config.sections = vec![
+            BriefingSectionConfig::new("weather"),
+            BriefingSectionConfig {
+                title: Some("Schedule".into()),
+                ..BriefingSectionConfig::new("calendar")
+            },
+            BriefingSectionConfig {
+                enabled: false,
+                ..BriefingSectionConfig::new("finance")
+            },
+        ];
+        let briefing = engine()
+            .generate(&config, BriefingPeriod::WeekAhead, now(), None)
+            .await
+            .unwrap();
+        let ids: Vec<_> = briefing.sections.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["weather", "calendar"]);
+        assert_eq!(briefing.sections[1].title, "Schedule");
+        assert!(
+            briefing
+                .title()
+                .ends_with("Week Ahead — 2026-03-02 to 2026-03-08")
+        );
+        assert!(briefing.to_markdown().contains("3 events (2026-03-08)"));
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_and_duplicate_sections() {
+        let engine = engine();
+        let mut config = config();
+        config.sections = vec![BriefingSectionConfig::new("horoscope")];
+        let err = engine.validate(&config).unwrap_err().to_string();
+        assert!(err.contains("'horoscope'"));
+        assert!(err.contains("calendar, finance, weather"));
+
+        config.sections = vec![
+            BriefingSectionConfig::new("calendar"),
+            BriefingSectionConfig::new("calendar"),
+        ];
+        assert!(matches!(
+            engine.validate(&config),
+            Err(BriefingError::DuplicateSection { .. })
+        ));
+
+        let mut config = super::BriefingConfig {
+            schedule: Some("not a cron".into()),
+            ..Default::default()
+        };
+        assert!(engine.validate(&config).is_err());
+        config.schedule = None;
+        config.timezone = Some("Mars/Olympus".into());
+        assert!(engine.validate(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_budget_truncates_busiest_section_without_provider() {
+        let long: String = (1..=60)
+            .map(|i| format!("- event number {} with a longish description\n", i))
+            .collect();
+        let mut engine = BriefingEngine::new(std::env::temp_dir());
+        engine.register(StaticSection::ok("calendar", &long));
+        engine.register(StaticSection::ok("flashcards", "2 cards due"));
+        let mut config = config();
+        config.max_tokens = 200;
+
+        let briefing = engine
+            .generate(&config, BriefingPeriod::Day, now(), None)
+            .await
+            .unwrap();
+        assert!(briefing.tokens <= 200, "got {} tokens", briefing.tokens);
+        assert_eq!(briefing.sections[0].status, SectionStatus::Summarized);
+        assert!(briefing.sections[0].body.contains("more"));
+        assert_eq!(briefing.sections[1].status, SectionStatus::Ok);
+        assert!(briefing.to_markdown().contains("2 cards due"));
+    }
+
+    #[tokio::test]
+    async fn test_budget_summarizes_with_provider() {
+        let long: String = (1..=60)
+            .map(|i| format!("- task {} is due soon\n", i))
+            .collect();
+        let mut engine = BriefingEngine::new(std::env::temp_dir());
+        engine.register(StaticSection::ok("deadlines", &long));
+        let mut config = config();
+        config.max_tokens = 100;
+        let provider: Arc<dyn LlmProvider> =
+            Arc::new(MockLlmProvider::with_response("- 60 tasks due this week"));
+
+        let briefing = engine
+            .generate(&config, BriefingPeriod::Day, now(), Some(provider))
+            .await
+            .unwrap();
+        assert_eq!(briefing.sections[0].body, "- 60 tasks due this week");
+        assert_eq!(briefing.sections[0].status, SectionStatus::Summarized);
+    }
+
+    #[test]
+    fn test_deliver_to_channel_and_canvas() {
+        let briefing = Briefing {
+            period: BriefingPeriod::Day,
+            generated_at: now(),
+            first_day: now().date_naive(),
+            last_day: now().date_naive(),
+            sections: Vec::new(),
+            tokens: 0,
+        };
+        let mut manager = ChannelManager::new();
+        let mut canvas = CanvasManager::new();
+
+        let delivered = briefing
+            .deliver(
+                &BriefingDelivery::Canvas { target: None },
+                &mut manager,
+                &mut canvas,
+            )
+            .unwrap();
+        assert!(
+            matches!(delivered, BriefingDelivered::Canvas { ref target, .. } if target == "broadcast")
+        );
+        assert_eq!(canvas.snapshot(&CanvasTarget::Broadcast).len(), 1);
+
+        let err = briefing
+            .deliver(
+                &BriefingDelivery::Channel {
+                    channel: "slack".into(),
+                    destination_id: "D1".into(),
+                },
+                &mut manager,
+                &mut canvas,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("'slack' is not registered"));
+    }
+
+    #[test]
+    fn test_cron_jobs_and_task_round_trip() {
+        let config: BriefingConfig = toml::from_str(
+            r#"
+            schedule = "0 30 7 * * MON-FRI"
+            week_ahead_schedule = "0 0 18 * * SUN"
+            timezone = "Europe/Berlin"
+            delivery = { type = "channel", channel = "slack", destination_id = "D1" }
+            "#,
+        )
+        .unwrap();
+        let jobs = config.cron_jobs();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].name, "briefing");
+        assert_eq!(jobs[1].timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(
+            BriefingPeriod::from_task(&jobs[1].task),
+            Some(BriefingPeriod::WeekAhead)
+        );
+        assert_eq!(BriefingPeriod::from_task("summarize inbox"), None);
+        assert_eq!(config.max_tokens, 1500);
+    }
+
+    #[tokio::test]
+    async fn test_core_sections_read_sessions_and_digests() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path();
+        let digests = workspace.join(".rustant").join("digests");
+        std::fs::create_dir_all(&digests).unwrap();
+        std::fs::write(
+            digests.join("digest_2026-03-02_0700.md"),
+            "# Channel Digest\n\n## Summary\n\nReceived 12 messages across 2 channels.\n\n\
+             ## Action Items\n\n- [ ] Reply to Dana (slack, high)\n- [x] Book room (email, low)\n",
+        )
+        .unwrap();
+        std::fs::write(digests.join("digest_2026-02-20_0700.md"), "old").unwrap();
+
+        let mut manager = crate::session_manager::SessionManager::new(workspace).unwrap();
+        let mut entry = manager.start_session(Some("refactor-parser"));
+        entry.updated_at = now() - Duration::hours(3);
+        entry.last_goal = Some("Refactor the parser".into());
+        let index = crate::session_manager::SessionIndex {
+            entries: vec![entry],
+        };
+        index.save(manager.sessions_dir()).unwrap();
+
+        let mut people = PersonGraph::default();
+        people.record_incoming(
+            crate::people::PersonObservation::from_handle("slack", "U1").with_name("Priya"),
+            now() - Duration::days(21),
+            "Any update on the report?",
+            Some("question".into()),
+            true,
+        );
+        people.save(workspace).unwrap();
+
+        let engine =
+            BriefingEngine::with_core_sections(workspace, &crate::config::AgentConfig::default());
+        let briefing = engine
+            .generate(&config(), BriefingPeriod::Day, now(), None)
+            .await
+            .unwrap();
+        let md = briefing.to_markdown();
+        assert!(md.contains("Received 12 messages across 2 channels."));
+        assert!(md.contains("- Reply to Dana (slack, high)"));
+        assert!(!md.contains("Book room"));
+        assert!(md.contains("**refactor-parser** — Refactor the parser (in progress"));
+        assert!(md.contains("1 unfinished session(s): refactor-parser"));
+        assert!(md.contains("- You haven't replied to Priya in 3 weeks"));
+    }
+}
@@ -0,0 +1,784 @@
+//! LLM response cache.
+//!
+//! When `[cache] enabled = true`, the [`Brain`](crate::brain::Brain) checks a
+//! process-wide [`ResponseCache`] before calling the provider. There are two layers:
+//!
+//! - **Exact** — keyed by a SHA-256 of the model, sampling parameters, message
+//!   roles and contents, and tool definitions. Message IDs and timestamps are
+//!   ignored, so rebuilding the same conversation hits.
+//! - **Semantic** (`semantic = true`) — on an exact miss, the user-authored text
+//!   is embedded and compared with cached entries whose *context* is identical:
+//!   same model, parameters, tools, and every non-user message (system prompt,
+//!   assistant turns, tool results). A cached response is returned when cosine
+//!   similarity reaches `similarity_threshold`.
+//!
+//! Served responses carry [`CompletionResponse::cache_hit`], so callers can see
+//! whether a response was semantic and retry with [`CachePolicy::ExactOnly`].
+//! Safety-relevant calls (approval prompt generation, injection scanning) must
+//! use `ExactOnly` or `Bypass`; they are never served from the semantic layer.
+//!
+//! Storage is bounded by `max_bytes` with least-recently-used eviction and is
+//! persisted as JSON under the data directory.
+
+use crate::search::{SimpleEmbedder, cosine_similarity};
+use crate::types::{CompletionRequest, CompletionResponse, Content, Message, Role, TokenUsage};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// File name of the persisted cache inside the data directory.
+const CACHE_FILE: &str = "llm_cache.json";
+/// Dimensionality of the user-content embeddings.
+const EMBEDDING_DIMENSIONS: usize = 256;
+/// Fixed per-entry overhead added to the measured payload size.
+const ENTRY_OVERHEAD_BYTES: usize = 256;
+
+/// Configuration for the LLM response cache (`[cache]`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Whether completions are cached.
+    pub enabled: bool,
+    /// Seconds a cached response stays valid.
+    pub ttl_secs: u64,
+    /// Upper bound on cache size; least recently used entries are evicted first.
+    pub max_bytes: usize,
+    /// Also serve requests whose user content is near-identical.
+    pub semantic: bool,
+    /// Minimum cosine similarity of user content for a semantic hit.
+    pub similarity_threshold: f32,
+    /// Persist the cache under the data directory between runs.
+    pub persist: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: 24 * 60 * 60,
+            max_bytes: 64 * 1024 * 1024,
+            semantic: false,
+            similarity_threshold: 0.97,
+            persist: true,
+        }
+    }
+}
+
+/// How a single request may use the cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Exact hits, plus semantic hits when the semantic layer is enabled.
+    #[default]
+    Full,
+    /// Exact hits only. Use for precision-critical and safety-relevant calls.
+    ExactOnly,
+    /// Neither read nor write the cache.
+    Bypass,
+}
+
+/// How a response was served from the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CacheHit {
+    /// The request matched a cached one exactly.
+    Exact,
+    /// The user content was similar enough to a cached request with the same context.
+    Semantic { similarity: f32 },
+}
+
+impl CacheHit {
+    /// Whether this was a semantic (approximate) hit.
+    pub fn is_semantic(&self) -> bool {
+        matches!(self, Self::Semantic { .. })
+    }
+}
+
+/// Hit-rate counters, kept globally by the cache and per task by the brain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub exact_hits: u64,
+    pub semantic_hits: u64,
+    pub misses: u64,
+    /// Provider tokens (input + output) not spent thanks to hits.
+    pub tokens_saved: u64,
+}
+
+impl CacheStats {
+    /// Total cache lookups.
+    pub fn lookups(&self) -> u64 {
+        self.exact_hits + self.semantic_hits + self.misses
+    }
+
+    /// Fraction of lookups served from the cache (0.0 when there were none).
+    pub fn hit_rate(&self) -> f64 {
+        match self.lookups() {
+            0 => 0.0,
+            n => (self.exact_hits + self.semantic_hits) as f64 / n as f64,
+        }
+    }
+
+    /// Count one lookup.
+    pub fn record(&mut self, hit: Option<CacheHit>, tokens_saved: u64) {
+        match hit {
+            Some(CacheHit::Exact) => self.exact_hits += 1,
+            Some(CacheHit::Semantic { .. }) => self.semantic_hits += 1,
+            None => self.misses += 1,
+        }
+        self.tokens_saved += tokens_saved;
+    }
+}
+
+impl std::fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} lookups hit ({:.0}%, {} semantic), {} tokens saved",
+            self.exact_hits + self.semantic_hits,
+            self.lookups(),
+            self.hit_rate() * 100.0,
+            self.semantic_hits,
+            self.tokens_saved
+        )
+    }
+}
+
+/// Errors loading or saving the persisted cache.
+#[derive(Debug, thiserror::Error)]
+pub enum CacheError {
+    #[error("Failed to access response cache {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Response cache {path} is corrupt: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    context: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    embedding: Vec<f32>,
+    message: Message,
+    model: String,
+    finish_reason: Option<String>,
+    usage: TokenUsage,
+    created_at: DateTime<Utc>,
+    bytes: usize,
+    /// LRU position; larger is more recent.
+    tick: u64,
+}
+
+impl CacheEntry {
+    fn tokens(&self) -> u64 {
+        self.usage.total() as u64
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedCache {
+    entries: HashMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    /// tick -> key, oldest first.
+    lru: BTreeMap<u64, String>,
+    next_tick: u64,
+    bytes: usize,
+    stats: CacheStats,
+    dirty: bool,
+}
+
+impl CacheState {
+    fn touch(&mut self, key: &str) {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.lru.remove(&entry.tick);
+            entry.tick = tick;
+            self.lru.insert(tick, key.to_string());
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<CacheEntry> {
+        let entry = self.entries.remove(key)?;
+        self.lru.remove(&entry.tick);
+        self.bytes -= entry.bytes;
+        self.dirty = true;
+        Some(entry)
+    }
+
+    fn insert(&mut self, key: String, mut entry: CacheEntry) {
+        self.remove(&key);
+        entry.tick = self.next_tick;
+        self.next_tick += 1;
+        self.bytes += entry.bytes;
+        self.lru.insert(entry.tick, key.clone());
+        self.entries.insert(key, entry);
+        self.dirty = true;
+    }
+
+    fn evict_to(&mut self, max_bytes: usize) {
+        while self.bytes > max_bytes {
+            let Some((_, key)) = self.lru.pop_first() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&key) {
+                self.bytes -= entry.bytes;
+            }
+            self.dirty = true;
+        }
+    }
+}
+
+/// Keys derived from a completion request.
+struct RequestKeys {
+    exact: String,
+    context: String,
+    user_text: String,
+}
+
+impl RequestKeys {
+    fn new(request: &CompletionRequest, model: &str) -> Self {
+        let mut params = Sha256::new();
+        params.update(model.as_bytes());
+        params.update([0]);
+        params.update(request.model.as_deref().unwrap_or("").as_bytes());
+        params.update(request.temperature.to_le_bytes());
+        params.update(request.max_tokens.unwrap_or(0).to_le_bytes());
+        for stop in &request.stop_sequences {
+            params.update(stop.as_bytes());
+            params.update([0]);
+        }
+        if let Some(tools) = &request.tools {
+            params.update(serde_json::to_vec(tools).unwrap_or_default());
+        }
+
+        let mut exact = params.clone();
+        let mut context = params;
+        let mut user_text = String::new();
+        for msg in &request.messages {
+            let content = serde_json::to_vec(&msg.content).unwrap_or_default();
+            exact.update(role_tag(msg.role).as_bytes());
+            exact.update(&content);
+            context.update(role_tag(msg.role).as_bytes());
+            if msg.role == Role::User {
+                // Only the position of user turns is part of the context.
+                collect_text(&msg.content, &mut user_text);
+                user_text.push('\n');
+            } else {
+                context.update(&content);
+            }
+        }
+
+        Self {
+            exact: hex(exact.finalize().as_slice()),
+            context: hex(context.finalize().as_slice()),
+            user_text,
+        }
+    }
+}
+
+fn role_tag(role: Role) -> &'static str {
+    match role {
+        Role::System => "\u{1}system",
+        Role::User => "\u{1}user",
+        Role::Assistant => "\u{1}assistant",
+        Role::Tool => "\u{1}tool",
+    }
+}
+
+fn collect_text(content: &Content, out: &mut String) {
+    match content {
+        Content::Text { text } => out.push_str(text),
+        Content::MultiPart { parts } => {
+            for part in parts {
+                collect_text(part, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_expired(entry: &CacheEntry, ttl_secs: u64) -> bool {
+    (Utc::now() - entry.created_at).num_seconds() >= ttl_secs as i64
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A bounded, optionally persisted cache of LLM responses.
+pub struct ResponseCache {
+    config: CacheConfig,
+    path: Option<PathBuf>,
+    embedder: SimpleEmbedder,
+    state: Mutex<CacheState>,
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("path", &self.path)
+            .field("semantic", &self.config.semantic)
+            .finish()
+    }
+}
+
+impl ResponseCache {
+    /// Create an in-memory cache.
+    pub fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            path: None,
+            embedder: SimpleEmbedder::new(EMBEDDING_DIMENSIONS),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Create a cache persisted at `path`, loading any entries already there.
+    ///
+    /// A missing file starts empty; expired entries are dropped on load.
+    pub fn open(config: CacheConfig, path: impl Into<PathBuf>) -> Result<Self, CacheError> {
+        let path = path.into();
+        let mut cache = Self::new(config);
+        if path.exists() {
+            let data = std::fs::read(&path).map_err(|source| CacheError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            let persisted: PersistedCache =
+                serde_json::from_slice(&data).map_err(|source| CacheError::Parse {
+                    path: path.clone(),
+                    source,
+                })?;
+            let mut entries: Vec<_> = persisted.entries.into_iter().collect();
+            entries.sort_by_key(|(_, e)| e.tick);
+            let ttl_secs = cache.config.ttl_secs;
+            let state = cache.state.get_mut().unwrap();
+            for (key, entry) in entries {
+                if !is_expired(&entry, ttl_secs) {
+                    state.insert(key, entry);
+                }
+            }
+            state.evict_to(cache.config.max_bytes);
+            state.dirty = false;
+        }
+        cache.path = Some(path);
+        Ok(cache)
+    }
+
+    /// The default persistence path under the platform data directory.
+    pub fn default_path() -> Option<PathBuf> {
+        directories::ProjectDirs::from("dev", "rustant", "rustant")
+            .map(|d| d.data_dir().join(CACHE_FILE))
+    }
+
+    /// The cache configuration.
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    /// Look up a response for `request` sent to `model`, along with the
+    /// provider tokens it saved.
+    ///
+    /// Hits come back with zero usage (nothing was spent) and `cache_hit` set.
+    /// Every lookup, including misses, counts toward [`stats`](Self::stats).
+    pub fn lookup(
+        &self,
+        request: &CompletionRequest,
+        model: &str,
+        policy: CachePolicy,
+    ) -> Option<(CompletionResponse, u64)> {
+        if policy == CachePolicy::Bypass {
+            return None;
+        }
+        let keys = RequestKeys::new(request, model);
+        let mut state = self.state.lock().unwrap();
+
+        let mut found = None;
+        if let Some(entry) = state.entries.get(&keys.exact) {
+            found = Some((
+                keys.exact.clone(),
+                CacheHit::Exact,
+                is_expired(entry, self.config.ttl_secs),
+            ));
+        } else if self.config.semantic && policy == CachePolicy::Full {
+            let embedding = self.embedder.embed(&keys.user_text);
+            found = state
+                .entries
+                .iter()
+                .filter(|(_, e)| e.context == keys.context && !e.embedding.is_empty())
+                .map(|(key, e)| (key, e, cosine_similarity(&embedding, &e.embedding)))
+                .filter(|(_, _, sim)| *sim >= self.config.similarity_threshold)
+                .max_by(|a, b| a.2.total_cmp(&b.2))
+                .map(|(key, e, similarity)| {
+                    (
+                        key.clone(),
+                        CacheHit::Semantic { similarity },
+                        is_expired(e, self.config.ttl_secs),
+                    )
+                });
+        }
+
+        let response = match found {
+            Some((key, _, true)) => {
+                state.remove(&key);
+                None
+            }
+            Some((key, hit, false)) => {
+                state.touch(&key);
+                let entry = &state.entries[&key];
+                Some((
+                    CompletionResponse {
+                        message: entry.message.clone(),
+                        usage: TokenUsage::default(),
+                        model: entry.model.clone(),
+                        finish_reason: entry.finish_reason.clone(),
+                        cache_hit: Some(hit),
+                    },
+                    entry.tokens(),
+                ))
+            }
+            None => None,
+        };
+
+        let saved = response.as_ref().map_or(0, |(_, tokens)| *tokens);
+        state
+            .stats
+            .record(response.as_ref().and_then(|(r, _)| r.cache_hit), saved);
+        response
+    }
+
+    /// Store a provider response for `request`.
+    ///
+    /// Responses that were themselves served from the cache are ignored.
+    pub fn store(
+        &self,
+        request: &CompletionRequest,
+        model: &str,
+        policy: CachePolicy,
+        response: &CompletionResponse,
+    ) {
+        if policy == CachePolicy::Bypass || response.cache_hit.is_some() {
+            return;
+        }
+        let keys = RequestKeys::new(request, model);
+        let embedding = if self.config.semantic {
+            self.embedder.embed(&keys.user_text)
+        } else {
+            Vec::new()
+        };
+        let bytes = serde_json::to_vec(&response.message).map_or(0, |v| v.len())
+            + embedding.len() * std::mem::size_of::<f32>()
+            + keys.context.len()
+            + keys.exact.len()
+            + ENTRY_OVERHEAD_BYTES;
+        if bytes > self.config.max_bytes {
+            return;
+        }
+
+        let entry = CacheEntry {
+            context: keys.context,
+            embedding,
+            message: response.message.clone(),
+            model: response.model.clone(),
+            finish_reason: response.finish_reason.clone(),
+            usage: response.usage,
+            created_at: Utc::now(),
+            bytes,
+            tick: 0,
+        };
+        let mut state = self.state.lock().unwrap();
+        state.insert(keys.exact, entry);
+        state.evict_to(self.config.max_bytes);
+    }
+
+    /// Lookup counters since the cache was created.
+    pub fn stats(&self) -> CacheStats {
+        self.state.lock().unwrap().stats
+    }
+
+    /// Number of entries and their total size in bytes.
+    pub fn usage(&self) -> (usize, usize) {
+        let state = self.state.lock().unwrap();
+        (state.entries.len(), state.bytes)
+    }
+
+    /// Drop every entry.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.entries.clear();
+        state.lru.clear();
+        state.bytes = 0;
+        state.dirty = true;
+    }
+
+    /// Write the cache to its persistence path if it changed since the last save.
+    pub fn flush(&self) -> Result<(), CacheError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut state = self.state.lock().unwrap();
+        if !state.dirty {
+            return Ok(());
+        }
+        let persisted = PersistedCache {
+            entries: state.entries.clone(),
+        };
+        write_atomic(path, &persisted)?;
+        state.dirty = false;
+        Ok(())
+    }
+}
+
+fn write_atomic(path: &Path, persisted: &PersistedCache) -> Result<(), CacheError> {
+    let io_err = |source| CacheError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    let data = serde_json::to_vec(persisted).map_err(|source| CacheError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, data).map_err(io_err)?;
+    std::fs::rename(&tmp, path).map_err(io_err)
+}
+
+/// The process-wide cache for `config`, shared by every agent so sub-tasks
+/// and spawned agents reuse each other's responses.
+///
+/// Returns `None` when caching is disabled. The first enabled config fixes
+/// the cache settings for the process. A persisted cache that fails to load
+/// is replaced with an empty one.
+pub fn shared_cache(config: &CacheConfig) -> Option<Arc<ResponseCache>> {
+    static SHARED: OnceLock<Arc<ResponseCache>> = OnceLock::new();
+    if !config.enabled {
+        return None;
+    }
+    let cache = SHARED.get_or_init(|| {
+        let path = config.persist.then(ResponseCache::default_path).flatten();
+        let cache = match path {
+            Some(path) => ResponseCache::open(config.clone(), &path).unwrap_or_else(|e| {
+                tracing::warn!("{}; starting with an empty response cache", e);
+                let _ = std::fs::remove_file(&path);
+                ResponseCache::open(config.clone(), path)
+                    .unwrap_or_else(|_| ResponseCache::new(config.clone()))
+            }),
+            None => ResponseCache::new(config.clone()),
+        };
+        Arc::new(cache)
+    });
+    Some(Arc::clone(cache))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ToolDefinition;
+
+    fn request(system: &str, user: &str) -> CompletionRequest {
+        CompletionRequest {
+            messages: vec![Message::system(system), Message::user(user)],
+            ..Default::default()
+        }
+    }
+
+    fn response(text: &str) -> CompletionResponse {
+        CompletionResponse {
+            message: Message::assistant(text),
+            usage: TokenUsage {
+                input_tokens: 100,
+                output_tokens: 20,
+            },
+            model: "test-model".into(),
+            finish_reason: Some("stop".into()),
+            cache_hit: None,
+        }
+    }
+
+    fn semantic_config() -> CacheConfig {
+        CacheConfig {
+            enabled: true,
+            semantic: true,
+            similarity_threshold: 0.9,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_exact_hit_ignores_message_ids_and_reports_zero_usage() {
+        let cache = ResponseCache::new(CacheConfig::default());
+        let req = request("You classify email.", "Is this spam?");
+        assert!(cache.lookup(&req, "m", CachePolicy::Full).is_none());
+        cache.store(&req, "m", CachePolicy::Full, &response("no"));
+
+        // Same content, fresh message IDs and timestamps.
+        let again = request("You classify email.", "Is this spam?");
+        let (hit, saved) = cache.lookup(&again, "m", CachePolicy::Full).unwrap();
+        assert_eq!(hit.cache_hit, Some(CacheHit::Exact));
+        assert_eq!(hit.message.content.as_text(), Some("no"));
+        assert_eq!(hit.usage.total(), 0);
+        assert_eq!(saved, 120);
+
+        // Different model or tools miss.
+        assert!(cache.lookup(&again, "other", CachePolicy::Full).is_none());
+        let mut with_tools = again.clone();
+        with_tools.tools = Some(vec![ToolDefinition {
+            name: "file_read".into(),
+            description: "Read a file".into(),
+            parameters: serde_json::json!({}),
+        }]);
+        assert!(cache.lookup(&with_tools, "m", CachePolicy::Full).is_none());
+
+        let stats = cache.stats();
+        assert_eq!(stats.exact_hits, 1);
+        assert_eq!(stats.misses, 3);
+        assert_eq!(stats.tokens_saved, 120);
+    }
+
+    #[test]
+    fn test_semantic_hit_requires_identical_context() {
+        let cache = ResponseCache::new(semantic_config());
+        let req = request(
+            "Summarize the file.",
+            "Summarize src/main.rs: the entry point parses arguments and starts the server",
+        );
+        cache.store(&req, "m", CachePolicy::Full, &response("summary"));
+
+        let similar = request(
+            "Summarize the file.",
+            "Summarize src/main.rs: the entry point parses arguments and starts the server now",
+        );
+        let (hit, _) = cache.lookup(&similar, "m", CachePolicy::Full).unwrap();
+        assert!(hit.cache_hit.unwrap().is_semantic());
+
+        // Precision-critical and safety-relevant callers opt out.
+        assert!(
+            cache
+                .lookup(&similar, "m", CachePolicy::ExactOnly)
+                .is_none()
+        );
+
+        // Same user text under a different system prompt is a different context.
+        let other_context = request(
+            "Translate the file.",
+            "Summarize src/main.rs: the entry point parses arguments and starts the server now",
+        );
+        assert!(
+            cache
+                .lookup(&other_context, "m", CachePolicy::Full)
+                .is_none()
+        );
+
+        let unrelated = request("Summarize the file.", "What is the weather in Paris?");
+        assert!(cache.lookup(&unrelated, "m", CachePolicy::Full).is_none());
+        assert_eq!(cache.stats().semantic_hits, 1);
+    }
+
+    #[test]
+    fn test_bypass_never_reads_or_writes() {
+        let cache = ResponseCache::new(CacheConfig::default());
+        let req = request("s", "u");
+        cache.store(&req, "m", CachePolicy::Bypass, &response("x"));
+        assert_eq!(cache.usage().0, 0);
+        cache.store(&req, "m", CachePolicy::Full, &response("x"));
+        assert!(cache.lookup(&req, "m", CachePolicy::Bypass).is_none());
+        assert_eq!(cache.stats().lookups(), 0);
+    }
+
+    #[test]
+    fn test_lru_eviction_by_bytes() {
+        let probe = ResponseCache::new(CacheConfig::default());
+        probe.store(&request("s", "0"), "m", CachePolicy::Full, &response("r0"));
+        let entry_bytes = probe.usage().1;
+
+        let cache = ResponseCache::new(CacheConfig {
+            max_bytes: entry_bytes * 2 + entry_bytes / 2,
+            ..Default::default()
+        });
+        cache.store(&request("s", "0"), "m", CachePolicy::Full, &response("r0"));
+        cache.store(&request("s", "1"), "m", CachePolicy::Full, &response("r1"));
+        // Touch 0 so 1 becomes least recently used.
+        assert!(
+            cache
+                .lookup(&request("s", "0"), "m", CachePolicy::Full)
+                .is_some()
+        );
+        cache.store(&request("s", "2"), "m", CachePolicy::Full, &response("r2"));
+
+        assert_eq!(cache.usage().0, 2);
+        assert!(cache.usage().1 <= cache.config().max_bytes);
+        assert!(
+            cache
+                .lookup(&request("s", "1"), "m", CachePolicy::Full)
+                .is_none()
+        );
+        assert!(
+            cache
+                .lookup(&request("s", "0"), "m", CachePolicy::Full)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_expired_entries_miss_and_are_removed() {
+        let cache = ResponseCache::new(CacheConfig {
+            ttl_secs: 0,
+            ..Default::default()
+        });
+        let req = request("s", "u");
+        cache.store(&req, "m", CachePolicy::Full, &response("x"));
+        assert!(cache.lookup(&req, "m", CachePolicy::Full).is_none());
+        assert_eq!(cache.usage(), (0, 0));
+    }
+
+    #[test]
+    fn test_persistence_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CACHE_FILE);
+        let req = request("s", "persist me");
+        {
+            let cache = ResponseCache::open(semantic_config(), &path).unwrap();
+            cache.store(&req, "m", CachePolicy::Full, &response("kept"));
+            cache.flush().unwrap();
+        }
+        let cache = ResponseCache::open(semantic_config(), &path).unwrap();
+        let (hit, _) = cache.lookup(&req, "m", CachePolicy::Full).unwrap();
+        assert_eq!(hit.message.content.as_text(), Some("kept"));
+
+        std::fs::write(&path, b"not json").unwrap();
+        assert!(matches!(
+            ResponseCache::open(semantic_config(), &path),
+            Err(CacheError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn test_stats_hit_rate_and_display() {
+        let mut stats = CacheStats::default();
+        assert_eq!(stats.hit_rate(), 0.0);
+        stats.record(Some(CacheHit::Exact), 10);
+        stats.record(Some(CacheHit::Semantic { similarity: 0.99 }), 5);
+        stats.record(None, 0);
+        stats.record(None, 0);
+        assert_eq!(stats.lookups(), 4);
+        assert!((stats.hit_rate() - 0.5).abs() < f64::EPSILON);
+        assert_eq!(
+            stats.to_string(),
+            "2 of 4 lookups hit (50%, 1 semantic), 15 tokens saved"
+        );
+    }
+}
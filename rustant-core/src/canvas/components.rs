@@ -3,20 +3,36 @@
 //! Defines structured specs for charts, tables, forms, and diagrams
 //! that the renderer converts to HTML/JS for display.
 
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Chart specification (rendered via Chart.js).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChartSpec {
     /// Chart type: "line", "bar", "pie", "scatter", "doughnut".
     pub chart_type: String,
-    /// Data labels (x-axis or category labels).
+    /// Data labels (x-axis or category labels). On a time x-axis these are
+    /// timestamps; on a linear or log x-axis, numbers.
     pub labels: Vec<String>,
     /// Dataset(s). Each dataset has a label and numeric data.
     pub datasets: Vec<ChartDataset>,
     /// Optional title for the chart.
     #[serde(default)]
     pub title: Option<String>,
+    /// X-axis settings. Defaults to a category axis.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x_axis: Option<AxisSpec>,
+    /// Primary (left) y-axis settings. Defaults to a linear axis.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y_axis: Option<AxisSpec>,
+    /// Secondary (right) y-axis, used by datasets with `"y_axis": "secondary"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y2_axis: Option<AxisSpec>,
+    /// Threshold and annotation lines.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<ChartAnnotation>,
 }
 
 /// A single dataset in a chart.
@@ -26,6 +42,150 @@ pub struct ChartDataset {
     pub data: Vec<f64>,
     #[serde(default)]
     pub color: Option<String>,
+    /// Which y-axis the dataset is plotted against.
+    #[serde(default, skip_serializing_if = "YAxis::is_primary")]
+    pub y_axis: YAxis,
+}
+
+/// How values along an axis are spaced and labelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AxisType {
+    /// Evenly spaced labels; the default x-axis.
+    Category,
+    /// Numbers on a linear scale; the default y-axis.
+    Linear,
+    /// Positive numbers on a base-10 logarithmic scale.
+    #[serde(alias = "logarithmic")]
+    Log,
+    /// Timestamps: RFC 3339 times, `YYYY-MM-DD[ HH:MM[:SS]]`, or Unix seconds.
+    Time,
+}
+
+impl fmt::Display for AxisType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AxisType::Category => "category",
+            AxisType::Linear => "linear",
+            AxisType::Log => "log",
+            AxisType::Time => "time",
+        })
+    }
+}
+
+/// Settings for one chart axis.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AxisSpec {
+    /// Axis type; defaults to `category` for x and `linear` for y.
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub axis_type: Option<AxisType>,
+    /// Axis title.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Lower bound of the axis (Unix seconds on a time axis).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    /// Upper bound of the axis (Unix seconds on a time axis).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// IANA timezone for time ticks and zoneless timestamps (default UTC).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// `strftime` format for time tick labels; chosen from the span if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+/// The y-axis a dataset is plotted against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum YAxis {
+    #[default]
+    #[serde(alias = "y", alias = "left")]
+    Primary,
+    #[serde(alias = "y2", alias = "right")]
+    Secondary,
+}
+
+impl YAxis {
+    fn is_primary(&self) -> bool {
+        *self == YAxis::Primary
+    }
+}
+
+/// The axis an annotation line is drawn against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationAxis {
+    /// A vertical line at an x position.
+    X,
+    /// A horizontal line on the primary y-axis.
+    #[default]
+    Y,
+    /// A horizontal line on the secondary y-axis.
+    Y2,
+}
+
+impl fmt::Display for AnnotationAxis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AnnotationAxis::X => "x",
+            AnnotationAxis::Y => "y",
+            AnnotationAxis::Y2 => "y2",
+        })
+    }
+}
+
+/// A threshold or marker line across the chart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartAnnotation {
+    #[serde(default)]
+    pub axis: AnnotationAxis,
+    /// Position on the axis: a number, a timestamp on a time axis, or a label
+    /// on a category axis.
+    pub value: AxisValue,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+/// A position on an axis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AxisValue {
+    Number(f64),
+    Text(String),
+}
+
+impl fmt::Display for AxisValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AxisValue::Number(n) => write!(f, "{}", n),
+            AxisValue::Text(t) => f.write_str(t),
+        }
+    }
+}
+
+/// A chart spec that cannot be rendered, with every offending field.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("invalid chart spec: {}", .issues.iter().map(|i| i.to_string()).collect::<Vec<_>>().join("; "))]
+pub struct ChartSpecError {
+    pub issues: Vec<ChartIssue>,
+}
+
+/// One problem in a chart spec.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartIssue {
+    /// Path of the offending field, e.g. `datasets[1].data[3]`.
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for ChartIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
 }
 
 impl ChartSpec {
@@ -38,8 +198,13 @@ impl ChartSpec {
                 label: "Data".into(),
                 data,
                 color: None,
+                y_axis: YAxis::Primary,
             }],
             title: None,
+            x_axis: None,
+            y_axis: None,
+            y2_axis: None,
+            annotations: Vec::new(),
         }
     }
 
@@ -50,6 +215,278 @@ impl ChartSpec {
             "line" | "bar" | "pie" | "scatter" | "doughnut" | "radar" | "polarArea"
         )
     }
+
+    /// Whether the chart has x and y axes (line, bar, scatter).
+    pub fn is_cartesian(&self) -> bool {
+        matches!(self.chart_type.as_str(), "line" | "bar" | "scatter")
+    }
+
+    /// The x-axis type, defaulting to `Category`.
+    pub fn x_axis_type(&self) -> AxisType {
+        axis_type(&self.x_axis, AxisType::Category)
+    }
+
+    /// The type of the primary or secondary y-axis, defaulting to `Linear`.
+    pub fn y_axis_type(&self, axis: YAxis) -> AxisType {
+        match axis {
+            YAxis::Primary => axis_type(&self.y_axis, AxisType::Linear),
+            YAxis::Secondary => axis_type(&self.y2_axis, AxisType::Linear),
+        }
+    }
+
+    /// Whether any dataset or annotation uses the secondary y-axis.
+    pub fn has_secondary_axis(&self) -> bool {
+        self.datasets.iter().any(|d| d.y_axis == YAxis::Secondary)
+            || self
+                .annotations
+                .iter()
+                .any(|a| a.axis == AnnotationAxis::Y2)
+    }
+
+    /// Timezone for time ticks and zoneless timestamps.
+    pub fn timezone(&self) -> chrono_tz::Tz {
+        self.x_axis
+            .as_ref()
+            .and_then(|a| a.timezone.as_deref())
+            .and_then(|tz| tz.parse().ok())
+            .unwrap_or(chrono_tz::UTC)
+    }
+
+    /// Numeric x positions for time (epoch milliseconds), linear and log
+    /// x-axes; `None` for a category axis.
+    pub fn x_values(&self) -> Option<Vec<f64>> {
+        let kind = self.x_axis_type();
+        if kind == AxisType::Category {
+            return None;
+        }
+        let tz = self.timezone();
+        Some(
+            self.labels
+                .iter()
+                .map(|l| axis_number(kind, &AxisValue::Text(l.clone()), &tz).unwrap_or(f64::NAN))
+                .collect(),
+        )
+    }
+
+    /// Position of an annotation on its axis: epoch milliseconds on a time
+    /// axis, a label index on a category axis.
+    pub fn annotation_position(&self, annotation: &ChartAnnotation) -> Option<f64> {
+        match annotation.axis {
+            AnnotationAxis::X => match self.x_axis_type() {
+                AxisType::Category => match &annotation.value {
+                    AxisValue::Text(label) => self
+                        .labels
+                        .iter()
+                        .position(|l| l == label)
+                        .map(|i| i as f64),
+                    AxisValue::Number(n) => Some(*n),
+                },
+                kind => axis_number(kind, &annotation.value, &self.timezone()),
+            },
+            AnnotationAxis::Y => axis_number(
+                self.y_axis_type(YAxis::Primary),
+                &annotation.value,
+                &chrono_tz::UTC,
+            ),
+            AnnotationAxis::Y2 => axis_number(
+                self.y_axis_type(YAxis::Secondary),
+                &annotation.value,
+                &chrono_tz::UTC,
+            ),
+        }
+    }
+
+    /// Check the spec, listing every offending field.
+    pub fn validate(&self) -> Result<(), ChartSpecError> {
+        let mut issues = Vec::new();
+        let mut issue = |field: String, message: String| issues.push(ChartIssue { field, message });
+
+        if !self.is_valid_type() {
+            issue(
+                "chart_type".into(),
+                format!(
+                    "unknown type '{}' (expected line, bar, scatter, pie, doughnut, radar or polarArea)",
+                    self.chart_type
+                ),
+            );
+        }
+        if self.datasets.is_empty() {
+            issue("datasets".into(), "at least one dataset is required".into());
+        }
+
+        let axes = [
+            ("x_axis", &self.x_axis),
+            ("y_axis", &self.y_axis),
+            ("y2_axis", &self.y2_axis),
+        ];
+        for (name, axis) in axes {
+            let Some(axis) = axis else { continue };
+            if !self.is_cartesian() {
+                issue(
+                    name.into(),
+                    format!("{} charts have no axes", self.chart_type),
+                );
+                continue;
+            }
+            if name != "x_axis"
+                && matches!(axis.axis_type, Some(AxisType::Category | AxisType::Time))
+            {
+                issue(
+                    format!("{}.type", name),
+                    "y-axes must be linear or log".into(),
+                );
+            }
+            if let Some(tz) = &axis.timezone
+                && tz.parse::<chrono_tz::Tz>().is_err()
+            {
+                issue(
+                    format!("{}.timezone", name),
+                    format!("unknown timezone '{}'", tz),
+                );
+            }
+            if let (Some(min), Some(max)) = (axis.min, axis.max)
+                && min >= max
+            {
+                issue(
+                    format!("{}.min", name),
+                    format!("min ({}) must be below max ({})", min, max),
+                );
+            }
+            if axis.axis_type == Some(AxisType::Log)
+                && let Some(min) = axis.min
+                && min <= 0.0
+            {
+                issue(
+                    format!("{}.min", name),
+                    "log axes need a positive minimum".into(),
+                );
+            }
+        }
+
+        let x_kind = self.x_axis_type();
+        if self.is_cartesian() && x_kind != AxisType::Category {
+            let tz = self.timezone();
+            for (i, label) in self.labels.iter().enumerate() {
+                if axis_number(x_kind, &AxisValue::Text(label.clone()), &tz).is_none() {
+                    let expected = match x_kind {
+                        AxisType::Time => "a timestamp",
+                        AxisType::Log => "a positive number",
+                        _ => "a number",
+                    };
+                    issue(
+                        format!("labels[{}]", i),
+                        format!("'{}' is not {} for the {} x-axis", label, expected, x_kind),
+                    );
+                }
+            }
+        }
+
+        for (i, dataset) in self.datasets.iter().enumerate() {
+            if !self.labels.is_empty() && dataset.data.len() > self.labels.len() {
+                issue(
+                    format!("datasets[{}].data", i),
+                    format!(
+                        "has {} values but there are {} labels",
+                        dataset.data.len(),
+                        self.labels.len()
+                    ),
+                );
+            }
+            if dataset.y_axis == YAxis::Secondary && !self.is_cartesian() {
+                issue(
+                    format!("datasets[{}].y_axis", i),
+                    format!("{} charts have no secondary axis", self.chart_type),
+                );
+            }
+            let log = self.is_cartesian() && self.y_axis_type(dataset.y_axis) == AxisType::Log;
+            for (j, value) in dataset.data.iter().enumerate() {
+                if !value.is_finite() {
+                    issue(
+                        format!("datasets[{}].data[{}]", i, j),
+                        "values must be finite".into(),
+                    );
+                } else if log && *value <= 0.0 {
+                    issue(
+                        format!("datasets[{}].data[{}]", i, j),
+                        format!("{} cannot be plotted on a log axis", value),
+                    );
+                }
+            }
+        }
+
+        for (i, annotation) in self.annotations.iter().enumerate() {
+            if !self.is_cartesian() {
+                issue(
+                    format!("annotations[{}]", i),
+                    format!("{} charts have no axes to annotate", self.chart_type),
+                );
+            } else if self.annotation_position(annotation).is_none() {
+                issue(
+                    format!("annotations[{}].value", i),
+                    format!(
+                        "'{}' is not a position on the {} axis",
+                        annotation.value, annotation.axis
+                    ),
+                );
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(ChartSpecError { issues })
+        }
+    }
+}
+
+fn axis_type(axis: &Option<AxisSpec>, default: AxisType) -> AxisType {
+    axis.as_ref().and_then(|a| a.axis_type).unwrap_or(default)
+}
+
+/// Numeric position of `value` on an axis of `kind`; epoch milliseconds for
+/// time axes.
+fn axis_number(kind: AxisType, value: &AxisValue, tz: &chrono_tz::Tz) -> Option<f64> {
+    let number = match (kind, value) {
+        (AxisType::Time, AxisValue::Number(secs)) => Some(secs * 1000.0),
+        (AxisType::Time, AxisValue::Text(text)) => {
+            parse_timestamp(text, tz).map(|t| t.timestamp_millis() as f64)
+        }
+        (_, AxisValue::Number(n)) => Some(*n),
+        (_, AxisValue::Text(text)) => text.trim().parse::<f64>().ok(),
+    }?;
+    let valid = number.is_finite() && (kind != AxisType::Log || number > 0.0);
+    valid.then_some(number)
+}
+
+/// Parse a timestamp label. Times without an offset are read in `tz`.
+pub fn parse_timestamp(text: &str, tz: &chrono_tz::Tz) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(text) {
+        return Some(t.with_timezone(&Utc));
+    }
+    if let Ok(secs) = text.parse::<i64>() {
+        return DateTime::from_timestamp(secs, 0);
+    }
+    let naive = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|f| NaiveDateTime::parse_from_str(text, f).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+    })?;
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(naive + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|t| t.with_timezone(&Utc))
 }
 
 /// Table specification (sortable HTML table).
@@ -75,7 +512,7 @@ impl TableSpec {
 }
 
 /// Form specification (validated HTML form).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FormSpec {
     /// Form fields.
     pub fields: Vec<FormField>,
@@ -85,6 +522,50 @@ pub struct FormSpec {
     /// Optional form title.
     #[serde(default)]
     pub title: Option<String>,
+    /// Seconds the form accepts submissions for; unlimited if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in_secs: Option<u64>,
+    /// Task started when the form is submitted and no task is waiting for
+    /// it. `{{name}}` placeholders are replaced by the submitted values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_submit: Option<String>,
+}
+
+impl FormSpec {
+    /// Look up a field by name.
+    pub fn field(&self, name: &str) -> Option<&FormField> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Problems that make the form unusable: no fields, or fields without
+    /// a name, with a duplicate name, or a select without options.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.fields.is_empty() {
+            problems.push("form has no fields".to_string());
+        }
+        let mut seen = std::collections::HashSet::new();
+        for field in &self.fields {
+            if field.name.trim().is_empty() {
+                problems.push(format!("field '{}' has no name", field.label));
+            } else if !seen.insert(field.name.as_str()) {
+                problems.push(format!("field name '{}' is used twice", field.name));
+            }
+            if field.field_type == "select" && field.options.is_empty() {
+                problems.push(format!("select field '{}' has no options", field.name));
+            }
+        }
+        problems
+    }
+
+    /// The spec as shown to clients: defaults of sensitive fields removed.
+    pub fn public(&self) -> FormSpec {
+        let mut spec = self.clone();
+        for field in spec.fields.iter_mut().filter(|f| f.sensitive) {
+            field.default_value = None;
+        }
+        spec
+    }
 }
 
 fn default_submit_text() -> String {
@@ -92,7 +573,7 @@ fn default_submit_text() -> String {
 }
 
 /// A form field definition.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FormField {
     /// Field name (used as form key).
     pub name: String,
@@ -112,6 +593,10 @@ pub struct FormField {
     /// Default value.
     #[serde(default)]
     pub default_value: Option<String>,
+    /// Secret input (password, token): rendered as a password field, never
+    /// echoed back to clients and redacted wherever the submission is recorded.
+    #[serde(default)]
+    pub sensitive: bool,
 }
 
 /// Diagram specification (rendered via Mermaid).
@@ -178,6 +663,86 @@ mod tests {
         assert!(!invalid.is_valid_type());
     }
 
+    #[test]
+    fn test_chart_axes_deserialize() {
+        let chart: ChartSpec = serde_json::from_value(serde_json::json!({
+            "chart_type": "line",
+            "labels": ["2026-03-29 01:30", "2026-03-29 02:30", "1774828800"],
+            "datasets": [
+                {"label": "a", "data": [1.0, 2.0, 3.0]},
+                {"label": "b", "data": [10.0, 100.0, 1000.0], "y_axis": "right"}
+            ],
+            "x_axis": {"type": "time", "timezone": "Europe/Berlin"},
+            "y2_axis": {"type": "logarithmic"},
+            "annotations": [{"value": 2.5, "label": "limit"}]
+        }))
+        .unwrap();
+        assert!(chart.validate().is_ok());
+        assert!(chart.has_secondary_axis());
+        assert_eq!(chart.y_axis_type(YAxis::Secondary), AxisType::Log);
+        let xs = chart.x_values().unwrap();
+        // 02:30 does not exist on the DST change; it resolves an hour later.
+        assert_eq!(xs[1] - xs[0], 3_600_000.0);
+        assert_eq!(xs[2], 1_774_828_800_000.0);
+    }
+
+    #[test]
+    fn test_chart_validation_names_fields() {
+        let mut chart = ChartSpec::simple("line", vec!["Mon".into()], vec![1.0, f64::NAN]);
+        chart.x_axis = Some(AxisSpec {
+            axis_type: Some(AxisType::Time),
+            timezone: Some("Mars/Olympus".into()),
+            ..Default::default()
+        });
+        chart.y_axis = Some(AxisSpec {
+            axis_type: Some(AxisType::Log),
+            ..Default::default()
+        });
+        chart.datasets[0].data[0] = -1.0;
+        chart.annotations.push(ChartAnnotation {
+            axis: AnnotationAxis::X,
+            value: AxisValue::Text("later".into()),
+            label: None,
+            color: None,
+        });
+        let err = chart.validate().unwrap_err();
+        let fields: Vec<&str> = err.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            [
+                "x_axis.timezone",
+                "labels[0]",
+                "datasets[0].data",
+                "datasets[0].data[0]",
+                "datasets[0].data[1]",
+                "annotations[0].value"
+            ]
+        );
+        assert!(
+            err.to_string()
+                .contains("labels[0]: 'Mon' is not a timestamp")
+        );
+    }
+
+    #[test]
+    fn test_chart_axes_rejected_on_pie() {
+        let mut chart = ChartSpec::simple("pie", vec!["A".into()], vec![1.0]);
+        chart.datasets[0].y_axis = YAxis::Secondary;
+        chart.y_axis = Some(AxisSpec::default());
+        let err = chart.validate().unwrap_err();
+        assert_eq!(err.issues[0].field, "y_axis");
+        assert_eq!(err.issues[1].field, "datasets[0].y_axis");
+    }
+
+    #[test]
+    fn test_simple_chart_serializes_without_axes() {
+        let chart = ChartSpec::simple("bar", vec!["A".into()], vec![1.0]);
+        let json = serde_json::to_value(&chart).unwrap();
+        assert!(json.get("x_axis").is_none());
+        assert!(json.get("annotations").is_none());
+        assert!(json["datasets"][0].get("y_axis").is_none());
+    }
+
     #[test]
     fn test_table_spec() {
         let table = TableSpec::new(
@@ -215,9 +780,12 @@ mod tests {
                 placeholder: Some("user@example.com".into()),
                 options: vec![],
                 default_value: None,
+                sensitive: false,
             }],
             submit_text: "Send".into(),
             title: Some("Contact".into()),
+            expires_in_secs: None,
+            on_submit: None,
         };
         assert_eq!(form.fields.len(), 1);
         assert!(form.fields[0].required);
@@ -234,13 +802,38 @@ mod tests {
                 placeholder: None,
                 options: vec![],
                 default_value: Some("default".into()),
+                sensitive: false,
             }],
             submit_text: "Submit".into(),
             title: None,
+            expires_in_secs: None,
+            on_submit: None,
         };
         let json = serde_json::to_string(&form).unwrap();
         let restored: FormSpec = serde_json::from_str(&json).unwrap();
         assert_eq!(restored.fields[0].default_value, Some("default".into()));
+        assert!(!json.contains("expires_in_secs"));
+    }
+
+    #[test]
+    fn test_form_spec_problems_and_public_view() {
+        let form: FormSpec = serde_json::from_value(serde_json::json!({
+            "fields": [
+                {"name": "token", "label": "Token", "field_type": "text",
+                 "sensitive": true, "default_value": "hunter2"},
+                {"name": "token", "label": "Again", "field_type": "text"},
+                {"name": "env", "label": "Env", "field_type": "select"}
+            ]
+        }))
+        .unwrap();
+        let problems = form.problems();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("used twice"));
+        assert!(problems[1].contains("no options"));
+
+        let public = form.public();
+        assert!(public.fields[0].default_value.is_none());
+        assert_eq!(form.fields[0].default_value.as_deref(), Some("hunter2"));
     }
 
     #[test]
@@ -0,0 +1,1291 @@
+//! Server-side chart export.
+//!
+//! Draws a [`ChartSpec`] as a standalone SVG, and rasterizes that SVG to PNG
+//! with resvg, so reports and PDFs can embed charts without a browser.
+
+use super::components::{AnnotationAxis, AxisSpec, AxisType, ChartSpec, ChartSpecError, YAxis};
+use super::renderer::escape_html;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use std::fmt::Write;
+use std::sync::{Arc, OnceLock};
+
+/// Colors for datasets (and slices) without an explicit color.
+const PALETTE: [&str; 8] = [
+    "#36a2eb", "#ff6384", "#4bc0c0", "#ff9f40", "#9966ff", "#ffcd56", "#8d99ae", "#2a9d8f",
+];
+const FONT: &str = "DejaVu Sans, Liberation Sans, Arial, Helvetica, sans-serif";
+const ANNOTATION_COLOR: &str = "#e03131";
+
+/// Size of an exported chart.
+#[derive(Debug, Clone, Copy)]
+pub struct ChartImageOptions {
+    /// Width in SVG user units (pixels at scale 1).
+    pub width: u32,
+    /// Height in SVG user units.
+    pub height: u32,
+    /// PNG pixels per SVG unit; 2.0 gives a sharp image for print.
+    pub scale: f32,
+}
+
+impl Default for ChartImageOptions {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 450,
+            scale: 1.0,
+        }
+    }
+}
+
+/// Errors from exporting a chart.
+#[derive(Debug, thiserror::Error)]
+pub enum ChartExportError {
+    #[error(transparent)]
+    Spec(#[from] ChartSpecError),
+    #[error("Failed to rasterize chart: {0}")]
+    Raster(String),
+}
+
+/// Render a chart as a standalone SVG document.
+pub fn render_chart_svg(
+    spec: &ChartSpec,
+    options: &ChartImageOptions,
+) -> Result<String, ChartSpecError> {
+    spec.validate()?;
+    let width = options.width.max(200) as f64;
+    let height = options.height.max(150) as f64;
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"{FONT}\" font-size=\"12\">\n\
+         <rect width=\"{w}\" height=\"{h}\" fill=\"#ffffff\"/>\n",
+        w = width,
+        h = height,
+    );
+    let mut top = 12.0;
+    if let Some(title) = &spec.title {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"28\" text-anchor=\"middle\" font-size=\"16\" font-weight=\"bold\" fill=\"#222\">{}</text>",
+            num(width / 2.0),
+            escape_html(title)
+        );
+        top += 28.0;
+    }
+    if spec.is_cartesian() {
+        draw_cartesian(&mut svg, spec, width, height, top);
+    } else {
+        draw_radial(&mut svg, spec, width, height, top);
+    }
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+/// Render a chart as a PNG image, on a white background.
+pub fn render_chart_png(
+    spec: &ChartSpec,
+    options: &ChartImageOptions,
+) -> Result<Vec<u8>, ChartExportError> {
+    let svg = render_chart_svg(spec, options)?;
+    svg_to_png(&svg, options.scale)
+}
+
+/// Rasterize an SVG document to an RGB PNG.
+pub fn svg_to_png(svg: &str, scale: f32) -> Result<Vec<u8>, ChartExportError> {
+    use resvg::{tiny_skia, usvg};
+
+    let scale = if scale.is_finite() && scale > 0.0 {
+        scale.min(8.0)
+    } else {
+        1.0
+    };
+    let options = usvg::Options {
+        fontdb: system_fonts(),
+        ..Default::default()
+    };
+    let tree =
+        usvg::Tree::from_str(svg, &options).map_err(|e| ChartExportError::Raster(e.to_string()))?;
+    let size = tree.size();
+    let width = (size.width() * scale).ceil() as u32;
+    let height = (size.height() * scale).ceil() as u32;
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| ChartExportError::Raster(format!("bad image size {width}x{height}")))?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    // The background is opaque, so dropping alpha loses nothing and keeps
+    // the PNG embeddable in PDFs.
+    let rgb: Vec<u8> = pixmap
+        .data()
+        .chunks_exact(4)
+        .flat_map(|p| [p[0], p[1], p[2]])
+        .collect();
+    encode_png(width, height, 3, &rgb).map_err(|e| ChartExportError::Raster(e.to_string()))
+}
+
+/// System fonts, loaded once.
+fn system_fonts() -> Arc<resvg::usvg::fontdb::Database> {
+    static FONTS: OnceLock<Arc<resvg::usvg::fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut db = resvg::usvg::fontdb::Database::new();
+            db.load_system_fonts();
+            Arc::new(db)
+        })
+        .clone()
+}
+
+/// Encode 8-bit grayscale (`channels` = 1) or RGB (`channels` = 3) pixels,
+/// row by row, as a PNG.
+pub(crate) fn encode_png(
+    width: u32,
+    height: u32,
+    channels: u8,
+    pixels: &[u8],
+) -> std::io::Result<Vec<u8>> {
+    use std::io::Write as _;
+
+    let stride = width as usize * channels as usize;
+    if pixels.len() != stride * height as usize {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "pixel buffer does not match the image size",
+        ));
+    }
+    // One filter byte (0 = none) per scanline.
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    for row in pixels.chunks_exact(stride.max(1)) {
+        encoder.write_all(&[0])?;
+        encoder.write_all(row)?;
+    }
+    let idat = encoder.finish()?;
+
+    let color_type = if channels == 3 { 2 } else { 0 };
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&width.to_be_bytes());
+    ihdr.extend_from_slice(&height.to_be_bytes());
+    // 8-bit samples, deflate, no filter method, no interlace.
+    ihdr.extend_from_slice(&[8, color_type, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png_chunk(&mut png, b"IHDR", &ihdr);
+    png_chunk(&mut png, b"IDAT", &idat);
+    png_chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let mut crc = flate2::Crc::new();
+    crc.update(kind);
+    crc.update(data);
+    out.extend_from_slice(&crc.sum().to_be_bytes());
+}
+
+// ---------------------------------------------------------------------------
+// Scales and ticks
+// ---------------------------------------------------------------------------
+
+/// Granularity of time-axis ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TimeUnit {
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year,
+}
+
+impl TimeUnit {
+    /// The unit Chart.js should use for a span of `span_ms` milliseconds.
+    pub(crate) fn for_span(span_ms: f64) -> Self {
+        const HOUR: f64 = 3_600_000.0;
+        if span_ms <= 2.0 * HOUR {
+            TimeUnit::Minute
+        } else if span_ms <= 72.0 * HOUR {
+            TimeUnit::Hour
+        } else if span_ms <= 120.0 * 24.0 * HOUR {
+            TimeUnit::Day
+        } else if span_ms <= 4.0 * 365.0 * 24.0 * HOUR {
+            TimeUnit::Month
+        } else {
+            TimeUnit::Year
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            TimeUnit::Minute => "minute",
+            TimeUnit::Hour => "hour",
+            TimeUnit::Day => "day",
+            TimeUnit::Month => "month",
+            TimeUnit::Year => "year",
+        }
+    }
+
+    /// Default `strftime` tick format.
+    pub(crate) fn strftime(self, span_ms: f64) -> &'static str {
+        match self {
+            TimeUnit::Minute => "%H:%M",
+            TimeUnit::Hour if span_ms <= 24.0 * 3_600_000.0 => "%H:%M",
+            TimeUnit::Hour => "%b %d %H:%M",
+            TimeUnit::Day => "%b %d",
+            TimeUnit::Month => "%b %Y",
+            TimeUnit::Year => "%Y",
+        }
+    }
+
+    fn approx_ms(self) -> f64 {
+        match self {
+            TimeUnit::Minute => 60_000.0,
+            TimeUnit::Hour => 3_600_000.0,
+            TimeUnit::Day => 86_400_000.0,
+            TimeUnit::Month => 2_629_746_000.0,
+            TimeUnit::Year => 31_556_952_000.0,
+        }
+    }
+}
+
+/// Map from data values to a 0..1 fraction of the axis length.
+#[derive(Debug, Clone, Copy)]
+enum Scale {
+    Linear {
+        lo: f64,
+        hi: f64,
+    },
+    Log {
+        lo: f64,
+        hi: f64,
+    },
+    /// `n` evenly spaced categories, each in the middle of its band.
+    Band {
+        n: usize,
+    },
+}
+
+impl Scale {
+    fn fraction(&self, v: f64) -> f64 {
+        match *self {
+            Scale::Linear { lo, hi } => (v - lo) / (hi - lo),
+            Scale::Log { lo, hi } => (v.log10() - lo.log10()) / (hi.log10() - lo.log10()),
+            Scale::Band { n } => (v + 0.5) / n.max(1) as f64,
+        }
+    }
+}
+
+struct Axis {
+    scale: Scale,
+    ticks: Vec<(f64, String)>,
+    title: Option<String>,
+}
+
+/// A linear axis covering `lo..hi`, with rounded bounds unless fixed.
+fn linear_axis(lo: f64, hi: f64, spec: Option<&AxisSpec>, max_ticks: usize) -> Axis {
+    let (mut lo, mut hi) = (lo, hi);
+    if let Some(min) = spec.and_then(|a| a.min) {
+        lo = min;
+    }
+    if let Some(max) = spec.and_then(|a| a.max) {
+        hi = max;
+    }
+    if !lo.is_finite() || !hi.is_finite() {
+        (lo, hi) = (0.0, 1.0);
+    }
+    if hi <= lo {
+        let pad = if lo == 0.0 { 1.0 } else { lo.abs() * 0.1 };
+        (lo, hi) = (lo - pad, hi + pad);
+    }
+    let step = nice_number((hi - lo) / max_ticks.max(2) as f64, true);
+    if spec.and_then(|a| a.min).is_none() {
+        lo = (lo / step).floor() * step;
+    }
+    if spec.and_then(|a| a.max).is_none() {
+        hi = (hi / step).ceil() * step;
+    }
+    let mut ticks = Vec::new();
+    let mut v = (lo / step).ceil() * step;
+    while v <= hi + step * 1e-9 {
+        ticks.push((v, format_number(v, step)));
+        v += step;
+    }
+    Axis {
+        scale: Scale::Linear { lo, hi },
+        ticks,
+        title: spec.and_then(|a| a.title.clone()),
+    }
+}
+
+/// A base-10 log axis covering `lo..hi` (both positive).
+fn log_axis(lo: f64, hi: f64, spec: Option<&AxisSpec>) -> Axis {
+    let (lo, hi) = if lo.is_finite() && hi.is_finite() && lo > 0.0 {
+        (lo, hi)
+    } else {
+        (1.0, 10.0)
+    };
+    let lo = spec
+        .and_then(|a| a.min)
+        .unwrap_or_else(|| 10f64.powf(lo.log10().floor()));
+    let hi = spec
+        .and_then(|a| a.max)
+        .unwrap_or_else(|| 10f64.powf(hi.log10().ceil()));
+    let hi = if hi > lo { hi } else { lo * 10.0 };
+    let ticks = (lo.log10().ceil() as i32..=hi.log10().floor() as i32)
+        .map(|e| {
+            let v = 10f64.powi(e);
+            (v, format_number(v, v))
+        })
+        .collect();
+    Axis {
+        scale: Scale::Log { lo, hi },
+        ticks,
+        title: spec.and_then(|a| a.title.clone()),
+    }
+}
+
+/// A time axis over epoch milliseconds, with ticks aligned in `tz`.
+fn time_axis(lo: f64, hi: f64, spec: &ChartSpec, max_ticks: usize) -> Axis {
+    let axis = spec.x_axis.as_ref();
+    let lo = axis.and_then(|a| a.min).map_or(lo, |s| s * 1000.0);
+    let hi = axis.and_then(|a| a.max).map_or(hi, |s| s * 1000.0);
+    let (lo, hi) = if hi > lo {
+        (lo, hi)
+    } else {
+        (lo - 1_800_000.0, hi + 1_800_000.0)
+    };
+    let tz = spec.timezone();
+    let span = hi - lo;
+    let format = axis
+        .and_then(|a| a.format.clone())
+        .unwrap_or_else(|| TimeUnit::for_span(span).strftime(span).to_string());
+    let ticks = time_ticks(lo, hi, &tz, max_ticks)
+        .into_iter()
+        .map(|ms| {
+            let label = DateTime::from_timestamp_millis(ms as i64)
+                .map(|t| t.with_timezone(&tz).format(&format).to_string())
+                .unwrap_or_default();
+            (ms, label)
+        })
+        .collect();
+    Axis {
+        scale: Scale::Linear { lo, hi },
+        ticks,
+        title: axis.and_then(|a| a.title.clone()),
+    }
+}
+
+/// Tick positions (epoch milliseconds) between `lo` and `hi`, at calendar
+/// boundaries in `tz`.
+fn time_ticks(lo: f64, hi: f64, tz: &chrono_tz::Tz, max_ticks: usize) -> Vec<f64> {
+    const STEPS: &[(TimeUnit, u32)] = &[
+        (TimeUnit::Minute, 1),
+        (TimeUnit::Minute, 5),
+        (TimeUnit::Minute, 15),
+        (TimeUnit::Minute, 30),
+        (TimeUnit::Hour, 1),
+        (TimeUnit::Hour, 3),
+        (TimeUnit::Hour, 6),
+        (TimeUnit::Hour, 12),
+        (TimeUnit::Day, 1),
+        (TimeUnit::Day, 2),
+        (TimeUnit::Day, 7),
+        (TimeUnit::Month, 1),
+        (TimeUnit::Month, 3),
+        (TimeUnit::Month, 6),
+        (TimeUnit::Year, 1),
+        (TimeUnit::Year, 5),
+        (TimeUnit::Year, 10),
+        (TimeUnit::Year, 100),
+    ];
+    let max_ticks = max_ticks.max(2) as f64;
+    let (unit, count) = STEPS
+        .iter()
+        .copied()
+        .find(|(unit, count)| (hi - lo) / (unit.approx_ms() * *count as f64) <= max_ticks)
+        .unwrap_or((TimeUnit::Year, 1000));
+
+    let Some(start) = DateTime::from_timestamp_millis(lo as i64) else {
+        return Vec::new();
+    };
+    let local = start.with_timezone(tz).naive_local();
+    let date = local.date();
+    let floor = |v: u32| v - v % count;
+    let mut cursor: NaiveDateTime = match unit {
+        TimeUnit::Minute => date.and_hms_opt(local.hour(), floor(local.minute()), 0),
+        TimeUnit::Hour => date.and_hms_opt(floor(local.hour()), 0, 0),
+        TimeUnit::Day => date.and_hms_opt(0, 0, 0),
+        TimeUnit::Month => NaiveDate::from_ymd_opt(date.year(), floor(date.month0()) + 1, 1)
+            .and_then(|d| d.and_hms_opt(0, 0, 0)),
+        TimeUnit::Year => {
+            let year = date.year() - date.year().rem_euclid(count as i32);
+            NaiveDate::from_ymd_opt(year, 1, 1).and_then(|d| d.and_hms_opt(0, 0, 0))
+        }
+    }
+    .unwrap_or(local);
+
+    let mut ticks = Vec::new();
+    // Bounded in case of a pathological step.
+    for _ in 0..1000 {
+        let Some(ms) = tz
+            .from_local_datetime(&cursor)
+            .earliest()
+            .or_else(|| {
+                tz.from_local_datetime(&(cursor + Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|t| t.timestamp_millis() as f64)
+        else {
+            break;
+        };
+        if ms > hi {
+            break;
+        }
+        if ms >= lo {
+            ticks.push(ms);
+        }
+        let next = match unit {
+            TimeUnit::Minute => cursor.checked_add_signed(Duration::minutes(count as i64)),
+            TimeUnit::Hour => cursor.checked_add_signed(Duration::hours(count as i64)),
+            TimeUnit::Day => cursor.checked_add_signed(Duration::days(count as i64)),
+            TimeUnit::Month => cursor.checked_add_months(Months::new(count)),
+            TimeUnit::Year => cursor.checked_add_months(Months::new(count * 12)),
+        };
+        match next {
+            Some(next) => cursor = next,
+            None => break,
+        }
+    }
+    ticks
+}
+
+/// Round `x` to 1, 2, 5 or 10 times a power of ten.
+fn nice_number(x: f64, round: bool) -> f64 {
+    if x <= 0.0 || !x.is_finite() {
+        return 1.0;
+    }
+    let exp = x.log10().floor();
+    let f = x / 10f64.powf(exp);
+    let nice = if round {
+        if f < 1.5 {
+            1.0
+        } else if f < 3.0 {
+            2.0
+        } else if f < 7.0 {
+            5.0
+        } else {
+            10.0
+        }
+    } else if f <= 1.0 {
+        1.0
+    } else if f <= 2.0 {
+        2.0
+    } else if f <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * 10f64.powf(exp)
+}
+
+/// Format a tick value with as many decimals as the tick step needs.
+fn format_number(v: f64, step: f64) -> String {
+    let abs = v.abs();
+    if abs >= 1e9 {
+        return format!("{}B", trim_decimals(v / 1e9, 2));
+    }
+    if abs >= 1e6 {
+        return format!("{}M", trim_decimals(v / 1e6, 2));
+    }
+    if abs >= 1e4 {
+        return format!("{}k", trim_decimals(v / 1e3, 1));
+    }
+    let decimals = if step > 0.0 && step < 1.0 {
+        (-step.log10().floor()) as usize
+    } else {
+        0
+    };
+    trim_decimals(v, decimals.min(6))
+}
+
+fn trim_decimals(v: f64, decimals: usize) -> String {
+    let s = format!("{:.*}", decimals, v);
+    let s = if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        s
+    };
+    if s == "-0" { "0".to_string() } else { s }
+}
+
+/// SVG coordinate with one decimal.
+fn num(v: f64) -> String {
+    trim_decimals(v, 1)
+}
+
+fn dataset_color(spec: &ChartSpec, i: usize) -> String {
+    spec.datasets[i]
+        .color
+        .clone()
+        .unwrap_or_else(|| PALETTE[i % PALETTE.len()].to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Cartesian charts
+// ---------------------------------------------------------------------------
+
+fn draw_cartesian(svg: &mut String, spec: &ChartSpec, width: f64, height: f64, mut top: f64) {
+    let secondary = spec.has_secondary_axis();
+    let is_bar = spec.chart_type == "bar";
+
+    // Legend
+    let named = spec.datasets.len() > 1 || spec.datasets.iter().any(|d| d.label != "Data");
+    if named {
+        let items: Vec<(String, String)> = (0..spec.datasets.len())
+            .map(|i| {
+                let mut label = spec.datasets[i].label.clone();
+                if secondary && spec.datasets[i].y_axis == YAxis::Secondary {
+                    label.push_str(" (right)");
+                }
+                (label, dataset_color(spec, i))
+            })
+            .collect();
+        draw_legend(svg, &items, width, top + 8.0);
+        top += 24.0;
+    }
+
+    let y_title = spec.y_axis.as_ref().and_then(|a| a.title.as_ref());
+    let y2_title = spec.y2_axis.as_ref().and_then(|a| a.title.as_ref());
+    let x_title = spec.x_axis.as_ref().and_then(|a| a.title.as_ref());
+    let left = 60.0 + if y_title.is_some() { 20.0 } else { 0.0 };
+    let right = if secondary { 60.0 } else { 24.0 } + if y2_title.is_some() { 20.0 } else { 0.0 };
+    let bottom = 40.0 + if x_title.is_some() { 20.0 } else { 0.0 };
+    let plot = Rect {
+        x: left,
+        y: top + 8.0,
+        w: (width - left - right).max(40.0),
+        h: (height - top - 8.0 - bottom).max(40.0),
+    };
+
+    // X axis
+    let x_values = spec.x_values();
+    let points = spec
+        .datasets
+        .iter()
+        .map(|d| d.data.len())
+        .max()
+        .unwrap_or(0)
+        .max(spec.labels.len());
+    let max_x_ticks = ((plot.w / 90.0) as usize).clamp(2, 12);
+    let x_axis = match (&x_values, spec.x_axis_type()) {
+        (Some(xs), kind) => {
+            let (lo, hi) = min_max(xs.iter().copied());
+            let (lo, hi) = if is_bar {
+                // Leave room for the first and last bars.
+                let gap = min_gap(xs).unwrap_or((hi - lo).max(1.0));
+                (lo - gap / 2.0, hi + gap / 2.0)
+            } else {
+                (lo, hi)
+            };
+            match kind {
+                AxisType::Time => time_axis(lo, hi, spec, max_x_ticks),
+                AxisType::Log => log_axis(lo, hi, spec.x_axis.as_ref()),
+                _ => linear_axis(lo, hi, spec.x_axis.as_ref(), max_x_ticks),
+            }
+        }
+        (None, _) => {
+            let n = points.max(1);
+            let widest = spec
+                .labels
+                .iter()
+                .map(|l| l.chars().count())
+                .max()
+                .unwrap_or(1);
+            let fits = (plot.w / (widest as f64 * 7.0 + 8.0)).max(1.0) as usize;
+            let stride = n.div_ceil(fits).max(1);
+            Axis {
+                scale: Scale::Band { n },
+                ticks: spec
+                    .labels
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| i % stride == 0)
+                    .map(|(i, l)| (i as f64, l.clone()))
+                    .collect(),
+                title: spec.x_axis.as_ref().and_then(|a| a.title.clone()),
+            }
+        }
+    };
+    let x_of = |i: usize| -> f64 {
+        match &x_values {
+            Some(xs) => xs.get(i).copied().unwrap_or(f64::NAN),
+            None => i as f64,
+        }
+    };
+
+    // Y axes
+    let max_y_ticks = ((plot.h / 50.0) as usize).clamp(2, 10);
+    let y_axis_for = |which: YAxis, axis_spec: Option<&AxisSpec>| -> Axis {
+        let mut values: Vec<f64> = spec
+            .datasets
+            .iter()
+            .filter(|d| d.y_axis == which)
+            .flat_map(|d| d.data.iter().copied())
+            .collect();
+        let annotation_axis = match which {
+            YAxis::Primary => AnnotationAxis::Y,
+            YAxis::Secondary => AnnotationAxis::Y2,
+        };
+        values.extend(
+            spec.annotations
+                .iter()
+                .filter(|a| a.axis == annotation_axis)
+                .filter_map(|a| spec.annotation_position(a)),
+        );
+        match spec.y_axis_type(which) {
+            AxisType::Log => {
+                let (lo, hi) = min_max(values.into_iter().filter(|v| *v > 0.0));
+                log_axis(lo, hi, axis_spec)
+            }
+            _ => {
+                if is_bar {
+                    values.push(0.0);
+                }
+                let (lo, hi) = min_max(values.into_iter());
+                linear_axis(lo, hi, axis_spec, max_y_ticks)
+            }
+        }
+    };
+    let y_axis = y_axis_for(YAxis::Primary, spec.y_axis.as_ref());
+    let y2_axis = secondary.then(|| y_axis_for(YAxis::Secondary, spec.y2_axis.as_ref()));
+    let axis_for = |which: YAxis| match which {
+        YAxis::Secondary => y2_axis.as_ref().unwrap_or(&y_axis),
+        YAxis::Primary => &y_axis,
+    };
+
+    let px = |v: f64| plot.x + x_axis.scale.fraction(v) * plot.w;
+    let py = |axis: &Axis, v: f64| plot.y + plot.h - axis.scale.fraction(v) * plot.h;
+
+    // Grid and tick labels
+    svg.push_str("<g stroke=\"#e9ecef\" stroke-width=\"1\">\n");
+    for (v, _) in &y_axis.ticks {
+        let y = py(&y_axis, *v);
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>",
+            num(plot.x),
+            num(y),
+            num(plot.x + plot.w),
+            num(y)
+        );
+    }
+    if x_values.is_some() {
+        for (v, _) in &x_axis.ticks {
+            let x = px(*v);
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>",
+                num(x),
+                num(plot.y),
+                num(x),
+                num(plot.y + plot.h)
+            );
+        }
+    }
+    svg.push_str("</g>\n<g fill=\"#555\">\n");
+    for (v, label) in &y_axis.ticks {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+            num(plot.x - 8.0),
+            num(py(&y_axis, *v) + 4.0),
+            escape_html(label)
+        );
+    }
+    if let Some(y2) = &y2_axis {
+        for (v, label) in &y2.ticks {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\">{}</text>",
+                num(plot.x + plot.w + 8.0),
+                num(py(y2, *v) + 4.0),
+                escape_html(label)
+            );
+        }
+    }
+    for (v, label) in &x_axis.ticks {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+            num(px(*v)),
+            num(plot.y + plot.h + 18.0),
+            escape_html(label)
+        );
+    }
+    svg.push_str("</g>\n");
+
+    // Axis titles
+    if let Some(title) = &x_axis.title {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"#333\">{}</text>",
+            num(plot.x + plot.w / 2.0),
+            num(plot.y + plot.h + 40.0),
+            escape_html(title)
+        );
+    }
+    if let Some(title) = &y_axis.title {
+        let (x, y) = (16.0, plot.y + plot.h / 2.0);
+        let _ = writeln!(
+            svg,
+            "<text x=\"{x}\" y=\"{y}\" text-anchor=\"middle\" fill=\"#333\" transform=\"rotate(-90 {x} {y})\">{}</text>",
+            escape_html(title),
+            x = num(x),
+            y = num(y)
+        );
+    }
+    if let Some(title) = y2_axis.as_ref().and_then(|a| a.title.as_ref()) {
+        let (x, y) = (width - 16.0, plot.y + plot.h / 2.0);
+        let _ = writeln!(
+            svg,
+            "<text x=\"{x}\" y=\"{y}\" text-anchor=\"middle\" fill=\"#333\" transform=\"rotate(90 {x} {y})\">{}</text>",
+            escape_html(title),
+            x = num(x),
+            y = num(y)
+        );
+    }
+
+    // Data, clipped to the plot area
+    let _ = writeln!(
+        svg,
+        "<clipPath id=\"plot\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/></clipPath>\n<g clip-path=\"url(#plot)\">",
+        num(plot.x),
+        num(plot.y),
+        num(plot.w),
+        num(plot.h)
+    );
+    let bar_count = spec.datasets.len().max(1) as f64;
+    let slot = match (&x_values, x_axis.scale) {
+        (None, Scale::Band { n }) => plot.w / n.max(1) as f64,
+        (Some(xs), Scale::Linear { lo, hi }) => {
+            min_gap(xs).map_or(plot.w / 4.0, |gap| plot.w * gap / (hi - lo))
+        }
+        _ => plot.w / points.max(4) as f64,
+    };
+    let group = slot * 0.8;
+    for (i, dataset) in spec.datasets.iter().enumerate() {
+        let color = escape_html(&dataset_color(spec, i));
+        let axis = axis_for(dataset.y_axis);
+        let points: Vec<(f64, f64)> = dataset
+            .data
+            .iter()
+            .enumerate()
+            .map(|(j, v)| (px(x_of(j)), py(axis, *v)))
+            .collect();
+        match spec.chart_type.as_str() {
+            "bar" => {
+                let bar = (group / bar_count).max(1.0);
+                let base = match axis.scale {
+                    Scale::Linear { lo, hi } => py(axis, 0f64.clamp(lo, hi)),
+                    _ => plot.y + plot.h,
+                };
+                for (x, y) in points
+                    .iter()
+                    .filter(|(x, y)| x.is_finite() && y.is_finite())
+                {
+                    let x0 = x - group / 2.0 + i as f64 * bar;
+                    let _ = writeln!(
+                        svg,
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" fill-opacity=\"0.8\"/>",
+                        num(x0),
+                        num(y.min(base)),
+                        num(bar),
+                        num((base - y).abs()),
+                        color
+                    );
+                }
+            }
+            "line" => {
+                // Gaps (unparseable x values) split the line.
+                for run in points
+                    .split(|(x, y)| !x.is_finite() || !y.is_finite())
+                    .filter(|r| !r.is_empty())
+                {
+                    let path: Vec<String> = run
+                        .iter()
+                        .map(|(x, y)| format!("{},{}", num(*x), num(*y)))
+                        .collect();
+                    let _ = writeln!(
+                        svg,
+                        "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+                        path.join(" "),
+                        color
+                    );
+                }
+                draw_points(svg, &points, 2.5, &color);
+            }
+            _ => draw_points(svg, &points, 3.5, &color),
+        }
+    }
+
+    // Annotations
+    for annotation in &spec.annotations {
+        let Some(value) = spec.annotation_position(annotation) else {
+            continue;
+        };
+        let color = escape_html(annotation.color.as_deref().unwrap_or(ANNOTATION_COLOR));
+        let (x1, y1, x2, y2, lx, ly, anchor) = match annotation.axis {
+            AnnotationAxis::X => {
+                let x = px(value);
+                (
+                    x,
+                    plot.y,
+                    x,
+                    plot.y + plot.h,
+                    x + 4.0,
+                    plot.y + 12.0,
+                    "start",
+                )
+            }
+            AnnotationAxis::Y | AnnotationAxis::Y2 => {
+                let which = if annotation.axis == AnnotationAxis::Y2 {
+                    YAxis::Secondary
+                } else {
+                    YAxis::Primary
+                };
+                let y = py(axis_for(which), value);
+                let right = plot.x + plot.w;
+                (plot.x, y, right, y, right - 4.0, y - 4.0, "end")
+            }
+        };
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"1.5\" stroke-dasharray=\"6 4\"/>",
+            num(x1),
+            num(y1),
+            num(x2),
+            num(y2),
+            color
+        );
+        if let Some(label) = &annotation.label {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"{}\" font-size=\"11\" fill=\"{}\">{}</text>",
+                num(lx),
+                num(ly),
+                anchor,
+                color,
+                escape_html(label)
+            );
+        }
+    }
+    svg.push_str("</g>\n");
+
+    // Axis lines
+    let _ = writeln!(
+        svg,
+        "<g stroke=\"#868e96\" stroke-width=\"1\"><line x1=\"{l}\" y1=\"{b}\" x2=\"{r}\" y2=\"{b}\"/><line x1=\"{l}\" y1=\"{t}\" x2=\"{l}\" y2=\"{b}\"/>{y2}</g>",
+        l = num(plot.x),
+        r = num(plot.x + plot.w),
+        t = num(plot.y),
+        b = num(plot.y + plot.h),
+        y2 = if secondary {
+            format!(
+                "<line x1=\"{r}\" y1=\"{t}\" x2=\"{r}\" y2=\"{b}\"/>",
+                r = num(plot.x + plot.w),
+                t = num(plot.y),
+                b = num(plot.y + plot.h)
+            )
+        } else {
+            String::new()
+        }
+    );
+}
+
+fn draw_points(svg: &mut String, points: &[(f64, f64)], radius: f64, color: &str) {
+    for (x, y) in points
+        .iter()
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+    {
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+            num(*x),
+            num(*y),
+            radius,
+            color
+        );
+    }
+}
+
+struct Rect {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+}
+
+fn min_max(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        })
+}
+
+/// Smallest positive distance between sorted x values.
+fn min_gap(xs: &[f64]) -> Option<f64> {
+    let mut sorted: Vec<f64> = xs.iter().copied().filter(|v| v.is_finite()).collect();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+        .windows(2)
+        .map(|w| w[1] - w[0])
+        .filter(|gap| *gap > 0.0)
+        .min_by(f64::total_cmp)
+}
+
+/// A centered row of colored swatches and labels.
+fn draw_legend(svg: &mut String, items: &[(String, String)], width: f64, y: f64) {
+    let item_width = |label: &str| 22.0 + label.chars().count() as f64 * 6.5 + 14.0;
+    let total: f64 = items.iter().map(|(l, _)| item_width(l)).sum();
+    let mut x = ((width - total) / 2.0).max(8.0);
+    svg.push_str("<g fill=\"#333\">\n");
+    for (label, color) in items {
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"14\" height=\"10\" fill=\"{}\"/><text x=\"{}\" y=\"{}\">{}</text>",
+            num(x),
+            num(y - 9.0),
+            escape_html(color),
+            num(x + 20.0),
+            num(y),
+            escape_html(label)
+        );
+        x += item_width(label);
+    }
+    svg.push_str("</g>\n");
+}
+
+// ---------------------------------------------------------------------------
+// Pie, doughnut, polar area and radar charts
+// ---------------------------------------------------------------------------
+
+fn draw_radial(svg: &mut String, spec: &ChartSpec, width: f64, height: f64, top: f64) {
+    let legend_width = 160.0;
+    let cx = (width - legend_width) / 2.0;
+    let cy = top + (height - top) / 2.0;
+    let r = ((width - legend_width).min(height - top) / 2.0 - 24.0).max(20.0);
+
+    if spec.chart_type == "radar" {
+        draw_radar(svg, spec, cx, cy, r);
+        let items: Vec<(String, String)> = (0..spec.datasets.len())
+            .map(|i| (spec.datasets[i].label.clone(), dataset_color(spec, i)))
+            .collect();
+        draw_side_legend(svg, &items, width - legend_width + 8.0, top + 16.0);
+        return;
+    }
+
+    let Some(dataset) = spec.datasets.first() else {
+        return;
+    };
+    let values: Vec<f64> = dataset.data.iter().map(|v| v.max(0.0)).collect();
+    let label_of = |i: usize| {
+        spec.labels
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| format!("#{}", i + 1))
+    };
+    let color_of = |i: usize| PALETTE[i % PALETTE.len()].to_string();
+    let total: f64 = values.iter().sum();
+    let max = values.iter().copied().fold(0.0, f64::max);
+    let inner = if spec.chart_type == "doughnut" {
+        r * 0.55
+    } else {
+        0.0
+    };
+
+    let mut angle = -std::f64::consts::FRAC_PI_2;
+    for (i, value) in values.iter().enumerate() {
+        let (sweep, outer) = if spec.chart_type == "polarArea" {
+            (
+                std::f64::consts::TAU / values.len().max(1) as f64,
+                if max > 0.0 { r * value / max } else { 0.0 },
+            )
+        } else if total > 0.0 {
+            (std::f64::consts::TAU * value / total, r)
+        } else {
+            (0.0, r)
+        };
+        if sweep > 0.0 && outer > 0.0 {
+            let _ = writeln!(
+                svg,
+                "<path d=\"{}\" fill=\"{}\" stroke=\"#ffffff\" stroke-width=\"1.5\"/>",
+                slice_path(cx, cy, inner, outer, angle, angle + sweep),
+                color_of(i)
+            );
+        }
+        angle += sweep;
+    }
+    let items: Vec<(String, String)> = (0..values.len())
+        .map(|i| (label_of(i), color_of(i)))
+        .collect();
+    draw_side_legend(svg, &items, width - legend_width + 8.0, top + 16.0);
+}
+
+/// SVG path for a pie or doughnut slice between two angles (radians).
+fn slice_path(cx: f64, cy: f64, inner: f64, outer: f64, a0: f64, a1: f64) -> String {
+    // A full circle cannot be drawn as one arc; stop just short of it.
+    let a1 = if a1 - a0 >= std::f64::consts::TAU {
+        a0 + std::f64::consts::TAU - 1e-4
+    } else {
+        a1
+    };
+    let large = if a1 - a0 > std::f64::consts::PI { 1 } else { 0 };
+    let point = |r: f64, a: f64| format!("{},{}", num(cx + r * a.cos()), num(cy + r * a.sin()));
+    if inner > 0.0 {
+        format!(
+            "M{} A{o},{o} 0 {large} 1 {} L{} A{i},{i} 0 {large} 0 {} Z",
+            point(outer, a0),
+            point(outer, a1),
+            point(inner, a1),
+            point(inner, a0),
+            o = num(outer),
+            i = num(inner),
+        )
+    } else {
+        format!(
+            "M{},{} L{} A{o},{o} 0 {large} 1 {} Z",
+            num(cx),
+            num(cy),
+            point(outer, a0),
+            point(outer, a1),
+            o = num(outer),
+        )
+    }
+}
+
+fn draw_radar(svg: &mut String, spec: &ChartSpec, cx: f64, cy: f64, r: f64) {
+    let n = spec.labels.len().max(
+        spec.datasets
+            .iter()
+            .map(|d| d.data.len())
+            .max()
+            .unwrap_or(0),
+    );
+    if n < 3 {
+        return;
+    }
+    let max = spec
+        .datasets
+        .iter()
+        .flat_map(|d| d.data.iter().copied())
+        .fold(0.0, f64::max);
+    let axis = linear_axis(0.0, max, None, 5);
+    let Scale::Linear { hi, .. } = axis.scale else {
+        return;
+    };
+    let angle =
+        |i: usize| -std::f64::consts::FRAC_PI_2 + std::f64::consts::TAU * i as f64 / n as f64;
+    let point = |i: usize, v: f64| {
+        let d = if hi > 0.0 { r * v.max(0.0) / hi } else { 0.0 };
+        (cx + d * angle(i).cos(), cy + d * angle(i).sin())
+    };
+    let polygon = |v: &dyn Fn(usize) -> f64| {
+        (0..n)
+            .map(|i| {
+                let (x, y) = point(i, v(i));
+                format!("{},{}", num(x), num(y))
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    svg.push_str("<g fill=\"none\" stroke=\"#dee2e6\">\n");
+    for (tick, _) in axis.ticks.iter().filter(|(t, _)| *t > 0.0) {
+        let _ = writeln!(svg, "<polygon points=\"{}\"/>", polygon(&|_| *tick));
+    }
+    for i in 0..n {
+        let (x, y) = point(i, hi);
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>",
+            num(cx),
+            num(cy),
+            num(x),
+            num(y)
+        );
+    }
+    svg.push_str("</g>\n<g fill=\"#555\" text-anchor=\"middle\">\n");
+    for (i, label) in spec.labels.iter().enumerate().take(n) {
+        let (x, y) = point(i, hi * 1.12);
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\">{}</text>",
+            num(x),
+            num(y + 4.0),
+            escape_html(label)
+        );
+    }
+    svg.push_str("</g>\n");
+    for (i, dataset) in spec.datasets.iter().enumerate() {
+        let color = escape_html(&dataset_color(spec, i));
+        let _ = writeln!(
+            svg,
+            "<polygon points=\"{}\" fill=\"{c}\" fill-opacity=\"0.2\" stroke=\"{c}\" stroke-width=\"2\"/>",
+            polygon(&|j| dataset.data.get(j).copied().unwrap_or(0.0)),
+            c = color
+        );
+    }
+}
+
+/// A column of colored swatches and labels.
+fn draw_side_legend(svg: &mut String, items: &[(String, String)], x: f64, y: f64) {
+    svg.push_str("<g fill=\"#333\">\n");
+    for (i, (label, color)) in items.iter().enumerate().take(20) {
+        let row = y + i as f64 * 18.0;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"14\" height=\"10\" fill=\"{}\"/><text x=\"{}\" y=\"{}\">{}</text>",
+            num(x),
+            num(row - 9.0),
+            escape_html(color),
+            num(x + 20.0),
+            num(row),
+            escape_html(label)
+        );
+    }
+    svg.push_str("</g>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::components::{AxisValue, ChartAnnotation, ChartDataset};
+
+    fn time_series() -> ChartSpec {
+        let mut spec = ChartSpec::simple(
+            "line",
+            vec![
+                "2026-03-01T00:00:00Z".into(),
+                "2026-03-02T00:00:00Z".into(),
+                "2026-03-03T00:00:00Z".into(),
+            ],
+            vec![10.0, 12.0, 9.0],
+        );
+        spec.x_axis = Some(AxisSpec {
+            axis_type: Some(AxisType::Time),
+            timezone: Some("America/New_York".into()),
+            ..Default::default()
+        });
+        spec.datasets[0].label = "Requests".into();
+        spec.datasets.push(ChartDataset {
+            label: "Latency".into(),
+            data: vec![120.0, 450.0, 300.0],
+            color: Some("#ff6384".into()),
+            y_axis: YAxis::Secondary,
+        });
+        spec.annotations.push(ChartAnnotation {
+            axis: AnnotationAxis::Y2,
+            value: AxisValue::Number(400.0),
+            label: Some("SLO".into()),
+            color: None,
+        });
+        spec
+    }
+
+    #[test]
+    fn test_time_axis_ticks_use_timezone() {
+        let spec = time_series();
+        let svg = render_chart_svg(&spec, &ChartImageOptions::default()).unwrap();
+        assert!(svg.starts_with("<svg"));
+        // The data ends at midnight UTC, which is still the evening of
+        // Mar 02 in New York; ticks fall on local midnight and noon.
+        assert!(svg.contains(">Mar 01 00:00<"), "{svg}");
+        assert!(svg.contains(">Mar 02 12:00<"));
+        assert!(!svg.contains(">Mar 03 00:00<"));
+        assert!(svg.contains(">SLO<"));
+        assert!(svg.contains(">Latency (right)<"));
+    }
+
+    #[test]
+    fn test_time_ticks_align_to_local_boundaries() {
+        let tz: chrono_tz::Tz = "Asia/Kolkata".parse().unwrap();
+        let lo = DateTime::parse_from_rfc3339("2026-01-01T00:10:00Z")
+            .unwrap()
+            .timestamp_millis() as f64;
+        let ticks = time_ticks(lo, lo + 6.0 * 3_600_000.0, &tz, 8);
+        assert!(!ticks.is_empty());
+        for ms in ticks {
+            let local = DateTime::from_timestamp_millis(ms as i64)
+                .unwrap()
+                .with_timezone(&tz);
+            assert_eq!(local.minute(), 0, "{local}");
+        }
+    }
+
+    #[test]
+    fn test_nice_linear_ticks() {
+        let axis = linear_axis(3.0, 97.0, None, 5);
+        let labels: Vec<&str> = axis.ticks.iter().map(|(_, l)| l.as_str()).collect();
+        assert_eq!(labels, ["0", "20", "40", "60", "80", "100"]);
+        assert_eq!(format_number(0.25, 0.05), "0.25");
+        assert_eq!(format_number(25_000.0, 5_000.0), "25k");
+    }
+
+    #[test]
+    fn test_svg_for_every_chart_type() {
+        for chart_type in [
+            "line",
+            "bar",
+            "scatter",
+            "pie",
+            "doughnut",
+            "radar",
+            "polarArea",
+        ] {
+            let spec = ChartSpec::simple(
+                chart_type,
+                vec!["A".into(), "B".into(), "C".into()],
+                vec![1.0, 2.0, 3.0],
+            );
+            let svg = render_chart_svg(&spec, &ChartImageOptions::default()).unwrap();
+            assert!(svg.trim_end().ends_with("</svg>"), "{chart_type}");
+        }
+    }
+
+    #[test]
+    fn test_labels_are_escaped() {
+        let mut spec = ChartSpec::simple("bar", vec!["<b>".into()], vec![1.0]);
+        spec.title = Some("A & B".into());
+        let svg = render_chart_svg(&spec, &ChartImageOptions::default()).unwrap();
+        assert!(svg.contains("A &amp; B"));
+        assert!(!svg.contains("<b>"));
+    }
+
+    #[test]
+    fn test_invalid_spec_is_not_rendered() {
+        let spec = ChartSpec::simple("sparkline", vec![], vec![1.0]);
+        let err = render_chart_svg(&spec, &ChartImageOptions::default()).unwrap_err();
+        assert_eq!(err.issues[0].field, "chart_type");
+    }
+
+    #[test]
+    fn test_png_export() {
+        let png = render_chart_png(
+            &time_series(),
+            &ChartImageOptions {
+                width: 400,
+                height: 240,
+                scale: 2.0,
+            },
+        )
+        .unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        // IHDR width and height are scaled.
+        assert_eq!(&png[16..20], &800u32.to_be_bytes());
+        assert_eq!(&png[20..24], &480u32.to_be_bytes());
+    }
+}
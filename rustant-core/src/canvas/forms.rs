@@ -0,0 +1,271 @@
+//! Form submissions — validation against a [`FormSpec`], redaction of
+//! sensitive fields, and binding values into task templates.
+
+use super::components::{FormField, FormSpec};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Placeholder recorded instead of a sensitive field's value.
+pub const REDACTED: &str = "[redacted]";
+
+/// Why one submitted field was rejected, for inline display next to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct FieldError {
+    /// The field's name.
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Check submitted `values` against the form's field definitions.
+///
+/// Returns the values normalized to their field types: numbers as JSON
+/// numbers, checkboxes as booleans, everything else as strings. Empty
+/// optional fields are left out; an unchecked checkbox is `false`. Every
+/// problem is reported, not just the first. Error messages never include
+/// the submitted value.
+pub fn validate_submission(
+    spec: &FormSpec,
+    values: &Map<String, Value>,
+) -> Result<Map<String, Value>, Vec<FieldError>> {
+    let mut normalized = Map::new();
+    let mut errors = Vec::new();
+
+    for name in values.keys() {
+        if spec.field(name).is_none() {
+            errors.push(FieldError::new(name, "is not a field of this form"));
+        }
+    }
+    for field in &spec.fields {
+        match normalize_field(field, values.get(&field.name)) {
+            Ok(Some(value)) => {
+                normalized.insert(field.name.clone(), value);
+            }
+            Ok(None) if field.required => {
+                errors.push(FieldError::new(&field.name, "is required"));
+            }
+            Ok(None) => {}
+            Err(message) => errors.push(FieldError::new(&field.name, message)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(normalized)
+    } else {
+        Err(errors)
+    }
+}
+
+/// The normalized value of one field, `None` if it was left empty.
+fn normalize_field(field: &FormField, value: Option<&Value>) -> Result<Option<Value>, String> {
+    if field.field_type == "checkbox" {
+        let checked = match value {
+            None | Some(Value::Null) => false,
+            Some(Value::Bool(b)) => *b,
+            Some(Value::String(s)) => match s.trim().to_lowercase().as_str() {
+                "" | "false" | "0" | "off" | "no" => false,
+                "true" | "1" | "on" | "yes" => true,
+                _ => return Err("must be checked or unchecked".to_string()),
+            },
+            Some(_) => return Err("must be checked or unchecked".to_string()),
+        };
+        // A required checkbox (e.g. "I agree") must be ticked.
+        return Ok((checked || !field.required).then_some(Value::Bool(checked)));
+    }
+
+    let text = match value {
+        None | Some(Value::Null) => return Ok(None),
+        // Secrets are kept exactly as typed.
+        Some(Value::String(s)) if field.sensitive => s.clone(),
+        Some(Value::String(s)) => s.trim().to_string(),
+        Some(Value::Number(n)) => n.to_string(),
+        Some(Value::Bool(b)) => b.to_string(),
+        Some(_) => return Err("must be a single value".to_string()),
+    };
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+
+    match field.field_type.as_str() {
+        "number" => {
+            let number = text
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| {
+                    text.parse::<f64>()
+                        .ok()
+                        .filter(|n| n.is_finite())
+                        .map(Value::from)
+                        .ok_or(())
+                })
+                .map_err(|_| "must be a number".to_string())?;
+            Ok(Some(number))
+        }
+        "email" if !is_email(&text) => Err("must be an email address".to_string()),
+        "select" if !field.options.contains(&text) => {
+            Err(format!("must be one of: {}", field.options.join(", ")))
+        }
+        _ => Ok(Some(Value::String(text))),
+    }
+}
+
+/// A plausible address: one `@`, a non-empty local part and a dotted domain.
+fn is_email(text: &str) -> bool {
+    let Some((local, domain)) = text.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !text.contains(char::is_whitespace)
+        && domain.split('.').filter(|part| !part.is_empty()).count() >= 2
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+}
+
+/// `values` with every sensitive field replaced by [`REDACTED`], for
+/// anything that is recorded or shown to clients.
+pub fn redact_submission(spec: &FormSpec, values: &Map<String, Value>) -> Map<String, Value> {
+    values
+        .iter()
+        .map(|(name, value)| {
+            let sensitive = spec.field(name).is_some_and(|f| f.sensitive);
+            let value = if sensitive {
+                Value::String(REDACTED.to_string())
+            } else {
+                value.clone()
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+/// Replace `{{name}}` placeholders in `template` with submitted values.
+///
+/// Task text is broadcast and kept in the session, so sensitive fields bind
+/// as [`REDACTED`]. Placeholders for fields left empty bind as "".
+pub fn bind_template(template: &str, spec: &FormSpec, values: &Map<String, Value>) -> String {
+    let redacted = redact_submission(spec, values);
+    let mut text = template.to_string();
+    for field in &spec.fields {
+        let value = match redacted.get(&field.name) {
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+        text = text.replace(&format!("{{{{{}}}}}", field.name), &value);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> FormSpec {
+        serde_json::from_value(json!({
+            "title": "Deploy",
+            "fields": [
+                {"name": "env", "label": "Environment", "field_type": "select",
+                 "required": true, "options": ["staging", "production"]},
+                {"name": "replicas", "label": "Replicas", "field_type": "number"},
+                {"name": "notify", "label": "Notify", "field_type": "email"},
+                {"name": "confirm", "label": "I understand", "field_type": "checkbox",
+                 "required": true},
+                {"name": "token", "label": "Token", "field_type": "text",
+                 "required": true, "sensitive": true}
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn values(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_valid_submission_is_normalized() {
+        let normalized = validate_submission(
+            &spec(),
+            &values(json!({
+                "env": "staging",
+                "replicas": "3",
+                "notify": "",
+                "confirm": "on",
+                "token": " s3cret "
+            })),
+        )
+        .unwrap();
+        assert_eq!(normalized["env"], "staging");
+        assert_eq!(normalized["replicas"], 3);
+        assert!(!normalized.contains_key("notify"));
+        assert_eq!(normalized["confirm"], true);
+        assert_eq!(normalized["token"], " s3cret ");
+    }
+
+    #[test]
+    fn test_invalid_submission_reports_every_field() {
+        let errors = validate_submission(
+            &spec(),
+            &values(json!({
+                "env": "qa",
+                "replicas": "many",
+                "notify": "ops@",
+                "token": "",
+                "extra": 1
+            })),
+        )
+        .unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec!["extra", "env", "replicas", "notify", "confirm", "token"]
+        );
+        assert_eq!(errors[1].message, "must be one of: staging, production");
+        assert_eq!(errors[2].message, "must be a number");
+        assert_eq!(errors[3].message, "must be an email address");
+        assert_eq!(errors[4].message, "is required");
+        assert!(errors.iter().all(|e| !e.message.contains("ops@")));
+    }
+
+    #[test]
+    fn test_redaction_and_template_binding() {
+        let spec = spec();
+        let submitted = values(json!({"env": "production", "replicas": 2, "token": "s3cret"}));
+        let redacted = redact_submission(&spec, &submitted);
+        assert_eq!(redacted["token"], REDACTED);
+        assert_eq!(redacted["env"], "production");
+
+        let text = bind_template(
+            "Deploy to {{env}} with {{replicas}} replicas ({{token}}, {{notify}})",
+            &spec,
+            &submitted,
+        );
+        assert_eq!(text, "Deploy to production with 2 replicas ([redacted], )");
+    }
+
+    #[test]
+    fn test_is_email() {
+        assert!(is_email("ops@example.com"));
+        assert!(!is_email("ops@example"));
+        assert!(!is_email("@example.com"));
+        assert!(!is_email("ops@@example.com"));
+        assert!(!is_email("o ps@example.com"));
+    }
+}
@@ -2,16 +2,25 @@
 //!
 //! A2UI-inspired protocol for agent-to-UI rich content display.
 //! Supports pushing HTML, Markdown, charts, tables, forms, and diagrams
-//! to connected UI clients (Tauri dashboard, web clients).
+//! to connected UI clients (Tauri dashboard, web clients). Form submissions
+//! are validated against their spec in [`forms`] before reaching the agent.
 
 pub mod components;
+pub mod export;
+pub mod forms;
 pub mod protocol;
 pub mod renderer;
 
-pub use components::{ChartDataset, ChartSpec, DiagramSpec, FormField, FormSpec, TableSpec};
+pub use components::{
+    AnnotationAxis, AxisSpec, AxisType, AxisValue, ChartAnnotation, ChartDataset, ChartIssue,
+    ChartSpec, ChartSpecError, DiagramSpec, FormField, FormSpec, TableSpec, YAxis,
+};
+pub use export::{ChartExportError, ChartImageOptions, render_chart_png, render_chart_svg};
+pub use forms::{FieldError, bind_template, redact_submission, validate_submission};
 pub use protocol::{CanvasItem, CanvasMessage, CanvasTarget, ContentType};
 pub use renderer::{
-    render_chart_config, render_diagram_mermaid, render_form_html, render_table_html,
+    render_chart_config, render_diagram_mermaid, render_form_html, render_form_html_for,
+    render_table_html,
 };
 
 use std::collections::HashMap;
@@ -1,10 +1,11 @@
 //! Canvas protocol — A2UI-inspired message types for agent-to-UI communication.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Messages exchanged between the agent and UI canvas.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum CanvasMessage {
     /// Push new content to the canvas.
@@ -43,7 +44,7 @@ pub enum CanvasMessage {
 }
 
 /// A content type for canvas items.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ContentType {
     Html,
@@ -74,7 +75,7 @@ impl ContentType {
 }
 
 /// Target for canvas operations.
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum CanvasTarget {
     /// Broadcast to all connected canvases.
@@ -85,7 +86,7 @@ pub enum CanvasTarget {
 }
 
 /// An item stored in the canvas state.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CanvasItem {
     pub id: Uuid,
     pub content_type: ContentType,
@@ -3,27 +3,53 @@
 //! Converts component specs (ChartSpec, TableSpec, FormSpec, DiagramSpec)
 //! into HTML/JS strings suitable for embedding in the dashboard or canvas UI.
 
-use super::components::{ChartSpec, DiagramSpec, FormSpec, TableSpec};
+use super::components::{
+    AnnotationAxis, AxisSpec, AxisType, AxisValue, ChartSpec, ChartSpecError, DiagramSpec,
+    FormSpec, TableSpec, YAxis,
+};
+use super::export::TimeUnit;
 
 /// Render a ChartSpec to a Chart.js config JSON string.
-pub fn render_chart_config(spec: &ChartSpec) -> String {
+///
+/// Axis settings become `options.scales` (time axes need a Chart.js date
+/// adapter such as chartjs-adapter-luxon) and annotations become
+/// chartjs-plugin-annotation lines. Specs without them render as before.
+pub fn render_chart_config(spec: &ChartSpec) -> Result<String, ChartSpecError> {
+    spec.validate()?;
+    let secondary = spec.has_secondary_axis();
+    let x_values = spec.x_values();
     let datasets: Vec<serde_json::Value> = spec
         .datasets
         .iter()
         .map(|ds| {
+            let data = match &x_values {
+                Some(xs) => serde_json::json!(
+                    xs.iter()
+                        .zip(&ds.data)
+                        .map(|(x, y)| serde_json::json!({"x": x, "y": y}))
+                        .collect::<Vec<_>>()
+                ),
+                None => serde_json::json!(ds.data),
+            };
             let mut obj = serde_json::json!({
                 "label": ds.label,
-                "data": ds.data,
+                "data": data,
             });
             if let Some(color) = &ds.color {
                 obj["borderColor"] = serde_json::json!(color);
                 obj["backgroundColor"] = serde_json::json!(color);
             }
+            if secondary {
+                obj["yAxisID"] = serde_json::json!(match ds.y_axis {
+                    YAxis::Primary => "y",
+                    YAxis::Secondary => "y2",
+                });
+            }
             obj
         })
         .collect();
 
-    let config = serde_json::json!({
+    let mut config = serde_json::json!({
         "type": spec.chart_type,
         "data": {
             "labels": spec.labels,
@@ -40,7 +66,168 @@ pub fn render_chart_config(spec: &ChartSpec) -> String {
         }
     });
 
-    serde_json::to_string_pretty(&config).unwrap_or_else(|_| "{}".into())
+    if spec.is_cartesian()
+        && (spec.x_axis.is_some() || spec.y_axis.is_some() || spec.y2_axis.is_some() || secondary)
+    {
+        let mut scales = serde_json::json!({
+            "x": scale_config(spec, spec.x_axis.as_ref(), spec.x_axis_type(), x_values.as_deref()),
+            "y": scale_config(spec, spec.y_axis.as_ref(), spec.y_axis_type(YAxis::Primary), None),
+        });
+        if secondary {
+            let mut y2 = scale_config(
+                spec,
+                spec.y2_axis.as_ref(),
+                spec.y_axis_type(YAxis::Secondary),
+                None,
+            );
+            y2["position"] = serde_json::json!("right");
+            y2["grid"] = serde_json::json!({"drawOnChartArea": false});
+            scales["y2"] = y2;
+        }
+        config["options"]["scales"] = scales;
+    }
+
+    if !spec.annotations.is_empty() {
+        let annotations: serde_json::Map<String, serde_json::Value> = spec
+            .annotations
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let value = match (a.axis, spec.x_axis_type()) {
+                    // The annotation plugin locates category positions by label.
+                    (AnnotationAxis::X, AxisType::Category) => match &a.value {
+                        AxisValue::Text(label) => serde_json::json!(label),
+                        AxisValue::Number(n) => serde_json::json!(n),
+                    },
+                    _ => serde_json::json!(spec.annotation_position(a)),
+                };
+                let color = a.color.as_deref().unwrap_or("#e03131");
+                let mut line = serde_json::json!({
+                    "type": "line",
+                    "scaleID": a.axis.to_string(),
+                    "value": value,
+                    "borderColor": color,
+                    "borderWidth": 2,
+                    "borderDash": [6, 4],
+                });
+                if let Some(label) = &a.label {
+                    line["label"] = serde_json::json!({
+                        "display": true,
+                        "content": label,
+                        "position": "end",
+                        "backgroundColor": color,
+                    });
+                }
+                (format!("annotation{}", i), line)
+            })
+            .collect();
+        config["options"]["plugins"]["annotation"] =
+            serde_json::json!({ "annotations": annotations });
+    }
+
+    Ok(serde_json::to_string_pretty(&config).unwrap_or_else(|_| "{}".into()))
+}
+
+/// Chart.js scale options for one axis.
+fn scale_config(
+    spec: &ChartSpec,
+    axis: Option<&AxisSpec>,
+    kind: AxisType,
+    values: Option<&[f64]>,
+) -> serde_json::Value {
+    let mut scale = serde_json::json!({
+        "type": match kind {
+            AxisType::Category => "category",
+            AxisType::Linear => "linear",
+            AxisType::Log => "logarithmic",
+            AxisType::Time => "time",
+        }
+    });
+    let ms = if kind == AxisType::Time { 1000.0 } else { 1.0 };
+    if let Some(axis) = axis {
+        if let Some(title) = &axis.title {
+            scale["title"] = serde_json::json!({"display": true, "text": title});
+        }
+        if let Some(min) = axis.min {
+            scale["min"] = serde_json::json!(min * ms);
+        }
+        if let Some(max) = axis.max {
+            scale["max"] = serde_json::json!(max * ms);
+        }
+    }
+    if kind == AxisType::Time {
+        let (lo, hi) = values
+            .unwrap_or_default()
+            .iter()
+            .filter(|v| v.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(*v), hi.max(*v))
+            });
+        let span = if hi > lo { hi - lo } else { 0.0 };
+        let unit = TimeUnit::for_span(span);
+        let format = axis
+            .and_then(|a| a.format.as_deref())
+            .unwrap_or_else(|| unit.strftime(span));
+        scale["time"] = serde_json::json!({
+            "unit": unit.name(),
+            "displayFormats": { unit.name(): strftime_to_luxon(format) },
+        });
+        scale["adapters"] = serde_json::json!({"date": {"zone": spec.timezone().name()}});
+    }
+    scale
+}
+
+/// Translate the common `strftime` specifiers to Luxon tokens, used by
+/// Chart.js' date adapter.
+fn strftime_to_luxon(format: &str) -> String {
+    let mut out = String::new();
+    let mut literal = String::new();
+    let flush = |out: &mut String, literal: &mut String| {
+        if !literal.is_empty() {
+            out.push('\'');
+            out.push_str(&literal.replace('\'', "''"));
+            out.push('\'');
+            literal.clear();
+        }
+    };
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            if c.is_ascii_alphabetic() || c == '\'' {
+                literal.push(c);
+            } else {
+                flush(&mut out, &mut literal);
+                out.push(c);
+            }
+            continue;
+        }
+        let token = match chars.next() {
+            Some('Y') => "yyyy",
+            Some('y') => "yy",
+            Some('m') => "LL",
+            Some('b') | Some('h') => "LLL",
+            Some('B') => "LLLL",
+            Some('d') => "dd",
+            Some('e') => "d",
+            Some('a') => "ccc",
+            Some('A') => "cccc",
+            Some('H') => "HH",
+            Some('I') => "hh",
+            Some('M') => "mm",
+            Some('S') => "ss",
+            Some('p') => "a",
+            Some('%') => {
+                flush(&mut out, &mut literal);
+                out.push('%');
+                continue;
+            }
+            _ => continue,
+        };
+        flush(&mut out, &mut literal);
+        out.push_str(token);
+    }
+    flush(&mut out, &mut literal);
+    out
 }
 
 /// Render a TableSpec to an HTML table string.
@@ -70,7 +257,23 @@ pub fn render_table_html(spec: &TableSpec) -> String {
 
 /// Render a FormSpec to an HTML form string.
 pub fn render_form_html(spec: &FormSpec) -> String {
-    let mut html = String::from("<form class=\"canvas-form\">\n");
+    render_form(spec, None)
+}
+
+/// Render a FormSpec registered with the gateway as `form_id`. The form
+/// posts to `/api/forms/{form_id}/submit` and has an empty
+/// `.form-error[data-error-for]` element after each field for inline errors.
+pub fn render_form_html_for(spec: &FormSpec, form_id: uuid::Uuid) -> String {
+    render_form(spec, Some(form_id))
+}
+
+fn render_form(spec: &FormSpec, form_id: Option<uuid::Uuid>) -> String {
+    let mut html = match form_id {
+        Some(id) => format!(
+            "<form class=\"canvas-form\" data-form-id=\"{id}\" method=\"post\" action=\"/api/forms/{id}/submit\">\n"
+        ),
+        None => String::from("<form class=\"canvas-form\">\n"),
+    };
     if let Some(title) = &spec.title {
         html.push_str(&format!("<h3>{}</h3>\n", escape_html(title)));
     }
@@ -90,10 +293,17 @@ pub fn render_form_html(spec: &FormSpec) -> String {
         let default_val = field
             .default_value
             .as_deref()
+            .filter(|_| !field.sensitive)
             .map(|v| format!(" value=\"{}\"", escape_html(v)))
             .unwrap_or_default();
 
-        match field.field_type.as_str() {
+        // Sensitive values are typed blind and never pre-filled.
+        let field_type = if field.sensitive {
+            "password"
+        } else {
+            field.field_type.as_str()
+        };
+        match field_type {
             "textarea" => {
                 let val = field.default_value.as_deref().unwrap_or("");
                 html.push_str(&format!(
@@ -147,6 +357,15 @@ pub fn render_form_html(spec: &FormSpec) -> String {
                     required,
                 ));
             }
+            "password" => {
+                html.push_str(&format!(
+                    "  <input type=\"password\" name=\"{}\" id=\"{}\" autocomplete=\"off\"{}{}>\n",
+                    escape_html(&field.name),
+                    escape_html(&field.name),
+                    placeholder,
+                    required,
+                ));
+            }
             _ => {
                 html.push_str(&format!(
                     "  <input type=\"{}\" name=\"{}\" id=\"{}\"{}{}{}>\n",
@@ -159,6 +378,12 @@ pub fn render_form_html(spec: &FormSpec) -> String {
                 ));
             }
         }
+        if form_id.is_some() {
+            html.push_str(&format!(
+                "  <div class=\"form-error\" data-error-for=\"{}\"></div>\n",
+                escape_html(&field.name)
+            ));
+        }
         html.push_str("</div>\n");
     }
     html.push_str(&format!(
@@ -179,7 +404,7 @@ pub fn render_diagram_mermaid(spec: &DiagramSpec) -> String {
 }
 
 /// Escape HTML special characters.
-fn escape_html(s: &str) -> String {
+pub(crate) fn escape_html(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -195,7 +420,7 @@ mod tests {
     #[test]
     fn test_render_chart_config_bar() {
         let spec = ChartSpec::simple("bar", vec!["A".into(), "B".into()], vec![1.0, 2.0]);
-        let json_str = render_chart_config(&spec);
+        let json_str = render_chart_config(&spec).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
         assert_eq!(parsed["type"], "bar");
         assert_eq!(parsed["data"]["labels"][0], "A");
@@ -206,7 +431,7 @@ mod tests {
     fn test_render_chart_config_line_with_title() {
         let mut spec = ChartSpec::simple("line", vec!["Jan".into()], vec![10.0]);
         spec.title = Some("Monthly".into());
-        let json_str = render_chart_config(&spec);
+        let json_str = render_chart_config(&spec).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
         assert_eq!(parsed["type"], "line");
         assert_eq!(parsed["options"]["plugins"]["title"]["display"], true);
@@ -220,7 +445,7 @@ mod tests {
             vec!["Red".into(), "Blue".into(), "Green".into()],
             vec![30.0, 50.0, 20.0],
         );
-        let json_str = render_chart_config(&spec);
+        let json_str = render_chart_config(&spec).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
         assert_eq!(parsed["type"], "pie");
     }
@@ -228,10 +453,81 @@ mod tests {
     #[test]
     fn test_render_chart_config_scatter() {
         let spec = ChartSpec::simple("scatter", vec!["1".into(), "2".into()], vec![5.0, 10.0]);
-        let json_str = render_chart_config(&spec);
+        let json_str = render_chart_config(&spec).unwrap();
         assert!(json_str.contains("scatter"));
     }
 
+    #[test]
+    fn test_render_chart_config_simple_has_no_scales() {
+        let spec = ChartSpec::simple("line", vec!["A".into()], vec![1.0]);
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_chart_config(&spec).unwrap()).unwrap();
+        assert!(parsed["options"].get("scales").is_none());
+        assert!(parsed["options"]["plugins"].get("annotation").is_none());
+        assert!(parsed["data"]["datasets"][0].get("yAxisID").is_none());
+    }
+
+    #[test]
+    fn test_render_chart_config_time_and_secondary_axes() {
+        let mut spec = ChartSpec::simple(
+            "line",
+            vec!["2026-05-01".into(), "2026-05-08".into()],
+            vec![3.0, 4.0],
+        );
+        spec.x_axis = Some(AxisSpec {
+            axis_type: Some(AxisType::Time),
+            timezone: Some("Asia/Tokyo".into()),
+            format: Some("%b %d".into()),
+            ..Default::default()
+        });
+        spec.datasets.push(ChartDataset {
+            label: "Errors".into(),
+            data: vec![1.0, 100.0],
+            color: None,
+            y_axis: YAxis::Secondary,
+        });
+        spec.y2_axis = Some(AxisSpec {
+            axis_type: Some(AxisType::Log),
+            ..Default::default()
+        });
+        spec.annotations.push(ChartAnnotation {
+            axis: AnnotationAxis::Y,
+            value: AxisValue::Number(3.5),
+            label: Some("target".into()),
+            color: None,
+        });
+        let parsed: serde_json::Value =
+            serde_json::from_str(&render_chart_config(&spec).unwrap()).unwrap();
+        let scales = &parsed["options"]["scales"];
+        assert_eq!(scales["x"]["type"], "time");
+        assert_eq!(scales["x"]["time"]["unit"], "day");
+        assert_eq!(scales["x"]["time"]["displayFormats"]["day"], "LLL dd");
+        assert_eq!(scales["x"]["adapters"]["date"]["zone"], "Asia/Tokyo");
+        assert_eq!(scales["y2"]["type"], "logarithmic");
+        assert_eq!(scales["y2"]["position"], "right");
+        let datasets = &parsed["data"]["datasets"];
+        assert_eq!(datasets[1]["yAxisID"], "y2");
+        // Midnight in Tokyo is 15:00 UTC the day before.
+        assert_eq!(datasets[0]["data"][0]["x"], 1_777_561_200_000.0);
+        let line = &parsed["options"]["plugins"]["annotation"]["annotations"]["annotation0"];
+        assert_eq!(line["scaleID"], "y");
+        assert_eq!(line["value"], 3.5);
+        assert_eq!(line["label"]["content"], "target");
+    }
+
+    #[test]
+    fn test_render_chart_config_rejects_invalid_spec() {
+        let spec = ChartSpec::simple("bar", vec!["A".into()], vec![1.0, 2.0]);
+        let err = render_chart_config(&spec).unwrap_err();
+        assert_eq!(err.issues[0].field, "datasets[0].data");
+    }
+
+    #[test]
+    fn test_strftime_to_luxon() {
+        assert_eq!(strftime_to_luxon("%Y-%m-%d %H:%M"), "yyyy-LL-dd HH:mm");
+        assert_eq!(strftime_to_luxon("Week of %b %e"), "'Week' 'of' LLL d");
+    }
+
     #[test]
     fn test_render_table_html() {
         let spec = TableSpec::new(
@@ -271,9 +567,12 @@ mod tests {
                 placeholder: Some("user@example.com".into()),
                 options: vec![],
                 default_value: None,
+                sensitive: false,
             }],
             submit_text: "Send".into(),
             title: Some("Contact".into()),
+            expires_in_secs: None,
+            on_submit: None,
         };
         let html = render_form_html(&spec);
         assert!(html.contains("<form"));
@@ -295,9 +594,12 @@ mod tests {
                 placeholder: None,
                 options: vec!["red".into(), "blue".into(), "green".into()],
                 default_value: Some("blue".into()),
+                sensitive: false,
             }],
             submit_text: "Submit".into(),
             title: None,
+            expires_in_secs: None,
+            on_submit: None,
         };
         let html = render_form_html(&spec);
         assert!(html.contains("<select"));
@@ -315,15 +617,34 @@ mod tests {
                 placeholder: Some("Enter notes...".into()),
                 options: vec![],
                 default_value: Some("Default notes".into()),
+                sensitive: false,
             }],
             submit_text: "Save".into(),
             title: None,
+            expires_in_secs: None,
+            on_submit: None,
         };
         let html = render_form_html(&spec);
         assert!(html.contains("<textarea"));
         assert!(html.contains("Default notes"));
     }
 
+    #[test]
+    fn test_render_form_html_for_hides_sensitive_values() {
+        let spec: FormSpec = serde_json::from_value(serde_json::json!({
+            "fields": [{"name": "api_key", "label": "API key", "field_type": "text",
+                        "required": true, "sensitive": true, "default_value": "sk-live"}]
+        }))
+        .unwrap();
+        let id = uuid::Uuid::new_v4();
+        let html = render_form_html_for(&spec, id);
+        assert!(html.contains(&format!("action=\"/api/forms/{}/submit\"", id)));
+        assert!(html.contains("type=\"password\""));
+        assert!(!html.contains("sk-live"));
+        assert!(html.contains("data-error-for=\"api_key\""));
+        assert!(!render_form_html(&spec).contains("data-error-for"));
+    }
+
     #[test]
     fn test_render_diagram_mermaid() {
         let spec = DiagramSpec::new("graph LR; A-->B; B-->C");
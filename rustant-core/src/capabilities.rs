@@ -0,0 +1,627 @@
+//! Paranoid-mode capability limits and per-task grants.
+//!
+//! Under [`ApprovalMode::Paranoid`](crate::config::ApprovalMode::Paranoid)
+//! approving a call is not enough: each call is first checked against limits
+//! that hold whatever the user answers.
+//!
+//! - **Writes.** The workspace is read-only. A file may be written, created or
+//!   deleted only under a grant for this task, and tools whose side effects
+//!   don't name a file need a grant for the tool. A missing grant is requested
+//!   through the normal approval flow; grants expire when the task ends.
+//!   `[safety.paranoid] write_grants` lists paths granted in every task.
+//!   Paths outside the workspace are never granted.
+//! - **Network.** Web, HTTP and browser tools may reach only hosts in
+//!   `network_allowlist`. Other requests are blocked before they are made.
+//! - **Shell.** `shell_exec` may run only the commands detected for the
+//!   project (see [`recommended_allowed_commands`]) and `extra_commands`, with
+//!   no shell operators, no paths leaving the workspace, and no arguments
+//!   matching `denied_arg_patterns`.
+//!
+//! Blocked calls reach the model as a [`CapabilityDenial`] and are recorded in
+//! the audit log.
+
+use crate::project_detect::{detect_project, recommended_allowed_commands};
+use crate::safety::{ActionDetails, SafetyGuardian};
+use crate::types::RiskLevel;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use tracing::warn;
+
+/// `[safety.paranoid]` — limits enforced in paranoid mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParanoidConfig {
+    /// Hosts that web, HTTP and browser tools may reach. `*.example.com`
+    /// matches subdomains; an empty list blocks all network access.
+    pub network_allowlist: Vec<String>,
+    /// Workspace path globs writable in every task without asking.
+    pub write_grants: Vec<String>,
+    /// Commands allowed in addition to those detected for the project.
+    pub extra_commands: Vec<String>,
+    /// Regular expressions; a shell command matching any of them is blocked.
+    pub denied_arg_patterns: Vec<String>,
+}
+
+impl Default for ParanoidConfig {
+    fn default() -> Self {
+        Self {
+            network_allowlist: Vec::new(),
+            write_grants: Vec::new(),
+            extra_commands: Vec::new(),
+            denied_arg_patterns: vec![
+                r"^git\s+(push|pull|fetch|clone|remote|config|submodule)\b".to_string(),
+                r"\b(install|publish|login|upload)\b".to_string(),
+                r"--force\b".to_string(),
+            ],
+        }
+    }
+}
+
+/// Something a tool call needs permission to do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Capability {
+    /// Write, create or delete a file (workspace-relative path or glob).
+    Write { path: String },
+    /// Side effects through a tool whose arguments don't name a file.
+    ToolWrite { tool: String },
+    /// Reach a network host.
+    Network { host: String },
+    /// Run a shell command.
+    Command { command: String },
+}
+
+impl Capability {
+    /// Short name of the capability kind, e.g. `write`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Capability::Write { .. } => "write",
+            Capability::ToolWrite { .. } => "tool_write",
+            Capability::Network { .. } => "network",
+            Capability::Command { .. } => "command",
+        }
+    }
+
+    /// The path, tool, host or command the capability applies to.
+    pub fn target(&self) -> &str {
+        match self {
+            Capability::Write { path } => path,
+            Capability::ToolWrite { tool } => tool,
+            Capability::Network { host } => host,
+            Capability::Command { command } => command,
+        }
+    }
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Capability::Write { path } => write!(f, "write access to '{}'", path),
+            Capability::ToolWrite { tool } => write!(f, "write access through '{}'", tool),
+            Capability::Network { host } => write!(f, "network access to '{}'", host),
+            Capability::Command { command } => write!(f, "running '{}'", command),
+        }
+    }
+}
+
+/// A blocked capability, returned to the model as a structured error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapabilityDenial {
+    pub capability: Capability,
+    pub reason: String,
+    /// What the model can do instead.
+    pub remedy: String,
+}
+
+impl std::fmt::Display for CapabilityDenial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = serde_json::json!({
+            "blocked": self.capability.kind(),
+            "target": self.capability.target(),
+            "reason": self.reason,
+            "remedy": self.remedy,
+        });
+        write!(f, "{}", value)
+    }
+}
+
+/// A write grant held for the current task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapabilityGrant {
+    /// A [`Capability::Write`] or [`Capability::ToolWrite`].
+    pub capability: Capability,
+    /// Tool whose call requested the grant, or `config` for `write_grants`.
+    pub requested_by: String,
+    pub granted_at: DateTime<Utc>,
+    /// Calls allowed by this grant so far.
+    pub uses: usize,
+}
+
+/// Outcome of checking a call's capabilities.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapabilityCheck {
+    Allowed,
+    /// Write capabilities the user may grant for the rest of the task.
+    NeedsGrant(Vec<Capability>),
+    Blocked(CapabilityDenial),
+}
+
+/// Enforces [`ParanoidConfig`] and tracks the current task's grants.
+#[derive(Debug, Clone)]
+pub struct CapabilityPolicy {
+    config: ParanoidConfig,
+    workspace: Option<PathBuf>,
+    commands: Vec<String>,
+    denied_args: Vec<(String, Regex)>,
+    grants: Vec<CapabilityGrant>,
+}
+
+impl CapabilityPolicy {
+    pub fn new(config: ParanoidConfig) -> Self {
+        let denied_args = config
+            .denied_arg_patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(re) => Some((pattern.clone(), re)),
+                Err(e) => {
+                    warn!(pattern = %pattern, "Ignoring invalid denied_arg_patterns entry: {}", e);
+                    None
+                }
+            })
+            .collect();
+        let mut policy = Self {
+            commands: config.extra_commands.clone(),
+            config,
+            workspace: None,
+            denied_args,
+            grants: Vec::new(),
+        };
+        policy.begin_task();
+        policy
+    }
+
+    /// Resolve paths against `workspace` and allow its project's commands.
+    pub fn set_workspace(&mut self, workspace: &Path) {
+        let mut commands = recommended_allowed_commands(&detect_project(workspace));
+        commands.extend(self.config.extra_commands.iter().cloned());
+        commands.sort();
+        commands.dedup();
+        self.commands = commands;
+        self.workspace = Some(workspace.to_path_buf());
+    }
+
+    /// Commands `shell_exec` may run.
+    pub fn allowed_commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    /// Drop the previous task's grants, keeping the configured ones.
+    pub fn begin_task(&mut self) {
+        let now = Utc::now();
+        self.grants = self
+            .config
+            .write_grants
+            .iter()
+            .map(|path| CapabilityGrant {
+                capability: Capability::Write { path: path.clone() },
+                requested_by: "config".to_string(),
+                granted_at: now,
+                uses: 0,
+            })
+            .collect();
+    }
+
+    /// Grants held for the current task.
+    pub fn grants(&self) -> &[CapabilityGrant] {
+        &self.grants
+    }
+
+    /// Grant `capability` for the rest of the task, counting this call as a use.
+    pub fn grant(&mut self, requested_by: &str, capability: Capability) {
+        self.grants.push(CapabilityGrant {
+            capability,
+            requested_by: requested_by.to_string(),
+            granted_at: Utc::now(),
+            uses: 1,
+        });
+    }
+
+    /// Check the capabilities a call needs.
+    ///
+    /// Grants that allow a capability have their use count incremented.
+    pub fn check(&mut self, capabilities: &[Capability]) -> CapabilityCheck {
+        let mut ungranted = Vec::new();
+        let mut used = Vec::new();
+        for capability in capabilities {
+            let normalized = match capability {
+                Capability::Write { path } => match self.workspace_relative(path) {
+                    Some(path) => Capability::Write { path },
+                    None => {
+                        return CapabilityCheck::Blocked(CapabilityDenial {
+                            capability: capability.clone(),
+                            reason: "path is outside the workspace".to_string(),
+                            remedy: "write inside the workspace instead".to_string(),
+                        });
+                    }
+                },
+                Capability::ToolWrite { .. } => capability.clone(),
+                Capability::Network { host } => {
+                    if let Some(reason) = self.network_blocked(host) {
+                        return CapabilityCheck::Blocked(CapabilityDenial {
+                            capability: capability.clone(),
+                            reason,
+                            remedy: "use an allowlisted host or work from local files".to_string(),
+                        });
+                    }
+                    continue;
+                }
+                Capability::Command { command } => {
+                    if let Some(reason) = self.command_blocked(command) {
+                        return CapabilityCheck::Blocked(CapabilityDenial {
+                            capability: capability.clone(),
+                            reason,
+                            remedy: format!(
+                                "run one plain command from: {}",
+                                self.commands.join(", ")
+                            ),
+                        });
+                    }
+                    continue;
+                }
+            };
+            match self
+                .grants
+                .iter()
+                .position(|grant| grant_covers(&grant.capability, &normalized))
+            {
+                Some(index) => used.push(index),
+                None => ungranted.push(normalized),
+            }
+        }
+        if !ungranted.is_empty() {
+            return CapabilityCheck::NeedsGrant(ungranted);
+        }
+        for index in used {
+            self.grants[index].uses += 1;
+        }
+        CapabilityCheck::Allowed
+    }
+
+    /// `path` relative to the workspace, or `None` if it leaves it.
+    fn workspace_relative(&self, path: &str) -> Option<String> {
+        if path.starts_with('~') {
+            return None;
+        }
+        let path = Path::new(path);
+        let relative = if path.is_absolute() {
+            path.strip_prefix(self.workspace.as_deref()?).ok()?
+        } else {
+            path
+        };
+        let mut parts = Vec::new();
+        for component in relative.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    parts.pop()?;
+                }
+                Component::RootDir | Component::Prefix(_) => return None,
+            }
+        }
+        Some(parts.join("/"))
+    }
+
+    fn network_blocked(&self, host: &str) -> Option<String> {
+        let host = host.split(':').next().unwrap_or(host).to_lowercase();
+        let allowed = self.config.network_allowlist.iter().any(|entry| {
+            let entry = entry.to_lowercase();
+            entry == host || (entry.starts_with("*.") && host.ends_with(&entry[1..]))
+        });
+        (!allowed).then(|| "host is not in [safety.paranoid] network_allowlist".to_string())
+    }
+
+    fn command_blocked(&self, command: &str) -> Option<String> {
+        let command = command.trim();
+        if command.contains(['\n', ';', '|', '&', '>', '<', '`']) || command.contains("$(") {
+            return Some("shell operators are not allowed in paranoid mode".to_string());
+        }
+        let allowed = self
+            .commands
+            .iter()
+            .any(|entry| command == entry || command.starts_with(&format!("{} ", entry)));
+        if !allowed {
+            return Some(format!(
+                "'{}' is not an allowed command for this project",
+                command.split_whitespace().next().unwrap_or("")
+            ));
+        }
+        for arg in command.split_whitespace().skip(1) {
+            let value = arg.split_once('=').map_or(arg, |(_, v)| v);
+            let value = value.trim_matches(['"', '\'']);
+            let path_like = value.starts_with(['/', '~']) || value.contains("..");
+            if path_like && self.workspace_relative(value).is_none() {
+                return Some(format!("argument '{}' leaves the workspace", arg));
+            }
+        }
+        self.denied_args
+            .iter()
+            .find(|(_, re)| re.is_match(command))
+            .map(|(pattern, _)| format!("command matches denied_arg_patterns '{}'", pattern))
+    }
+}
+
+/// Whether `grant` allows `capability` (both workspace-normalised).
+fn grant_covers(grant: &Capability, capability: &Capability) -> bool {
+    match (grant, capability) {
+        (Capability::Write { path: pattern }, Capability::Write { path }) => {
+            pattern == path || SafetyGuardian::glob_matches(pattern, path)
+        }
+        (Capability::ToolWrite { tool: granted }, Capability::ToolWrite { tool }) => {
+            granted == tool
+        }
+        _ => false,
+    }
+}
+
+/// Capabilities a call of `tool` needs in paranoid mode.
+pub fn required_capabilities(
+    tool: &str,
+    risk_level: RiskLevel,
+    details: &ActionDetails,
+    arguments: &serde_json::Value,
+) -> Vec<Capability> {
+    if tool == "shell_exec" {
+        return match details {
+            ActionDetails::ShellCommand { command } => vec![Capability::Command {
+                command: command.clone(),
+            }],
+            _ => Vec::new(),
+        };
+    }
+
+    let mut capabilities = Vec::new();
+    let mut add_host = |host: String| {
+        let capability = Capability::Network { host };
+        if !capabilities.contains(&capability) {
+            capabilities.push(capability);
+        }
+    };
+    match details {
+        ActionDetails::NetworkRequest { host, .. } => add_host(host.clone()),
+        ActionDetails::BrowserAction { url: Some(url), .. } => {
+            if let Some(host) = url_host(url) {
+                add_host(host);
+            }
+        }
+        _ => {}
+    }
+    if let Some(host) = arguments["url"].as_str().and_then(url_host) {
+        add_host(host);
+    }
+
+    match details {
+        ActionDetails::FileWrite { path, .. } | ActionDetails::FileDelete { path } => {
+            capabilities.push(Capability::Write {
+                path: path.to_string_lossy().into_owned(),
+            });
+        }
+        _ if risk_level >= RiskLevel::Write && capabilities.is_empty() => {
+            let path = ["path", "file_path", "file"]
+                .iter()
+                .find_map(|key| arguments[*key].as_str());
+            capabilities.push(match path {
+                Some(path) => Capability::Write {
+                    path: path.to_string(),
+                },
+                None => Capability::ToolWrite {
+                    tool: tool.to_string(),
+                },
+            });
+        }
+        _ => {}
+    }
+    capabilities
+}
+
+fn url_host(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(|host| host.to_string())
+}
+
+/// One-line summary of the grants a task used, e.g. for the REPL.
+pub fn summarize_grants(grants: &[CapabilityGrant]) -> Option<String> {
+    let used: Vec<String> = grants
+        .iter()
+        .filter(|grant| grant.uses > 0)
+        .map(|grant| {
+            format!(
+                "{} {} ({}×, {})",
+                grant.capability.kind(),
+                grant.capability.target(),
+                grant.uses,
+                grant.requested_by
+            )
+        })
+        .collect();
+    (!used.is_empty()).then(|| used.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy() -> CapabilityPolicy {
+        let mut policy = CapabilityPolicy::new(ParanoidConfig {
+            network_allowlist: vec!["docs.rs".into(), "*.github.com".into()],
+            write_grants: vec!["notes/**".into()],
+            ..ParanoidConfig::default()
+        });
+        policy.workspace = Some(PathBuf::from("/work"));
+        policy.commands = vec!["cargo".into(), "git".into(), "python -m pytest".into()];
+        policy
+    }
+
+    fn write(path: &str) -> Capability {
+        Capability::Write { path: path.into() }
+    }
+
+    fn blocked_reason(check: CapabilityCheck) -> String {
+        match check {
+            CapabilityCheck::Blocked(denial) => denial.reason,
+            other => panic!("expected Blocked, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_writes_need_grants_inside_workspace() {
+        let mut policy = policy();
+        assert_eq!(
+            policy.check(&[write("./src/../src/lib.rs")]),
+            CapabilityCheck::NeedsGrant(vec![write("src/lib.rs")])
+        );
+        assert_eq!(
+            policy.check(&[write("/work/notes/today.md")]),
+            CapabilityCheck::Allowed
+        );
+        assert!(blocked_reason(policy.check(&[write("../etc/passwd")])).contains("outside"));
+        assert!(blocked_reason(policy.check(&[write("/etc/hosts")])).contains("outside"));
+
+        policy.grant("file_write", write("src/lib.rs"));
+        assert_eq!(
+            policy.check(&[write("src/lib.rs")]),
+            CapabilityCheck::Allowed
+        );
+        assert_eq!(
+            summarize_grants(policy.grants()).as_deref(),
+            Some("write notes/** (1×, config); write src/lib.rs (2×, file_write)")
+        );
+
+        policy.begin_task();
+        assert_eq!(policy.grants().len(), 1);
+        assert!(summarize_grants(policy.grants()).is_none());
+    }
+
+    #[test]
+    fn test_network_limited_to_allowlist() {
+        let mut policy = policy();
+        let host = |h: &str| Capability::Network { host: h.into() };
+        assert_eq!(policy.check(&[host("docs.rs")]), CapabilityCheck::Allowed);
+        assert_eq!(
+            policy.check(&[host("api.github.com:443")]),
+            CapabilityCheck::Allowed
+        );
+        assert!(
+            blocked_reason(policy.check(&[host("evil.example")])).contains("network_allowlist")
+        );
+    }
+
+    #[test]
+    fn test_shell_commands_and_arguments_checked() {
+        let mut policy = policy();
+        let run = |policy: &mut CapabilityPolicy, c: &str| {
+            policy.check(&[Capability::Command { command: c.into() }])
+        };
+        assert_eq!(
+            run(&mut policy, "cargo test -p core"),
+            CapabilityCheck::Allowed
+        );
+        assert_eq!(
+            run(&mut policy, "python -m pytest tests/"),
+            CapabilityCheck::Allowed
+        );
+        assert!(
+            blocked_reason(run(&mut policy, "curl https://x.sh"))
+                .contains("not an allowed command")
+        );
+        assert!(blocked_reason(run(&mut policy, "python script.py")).contains("not an allowed"));
+        assert!(blocked_reason(run(&mut policy, "cargo test && rm -rf /")).contains("operators"));
+        assert!(
+            blocked_reason(run(&mut policy, "cargo run -- /etc/shadow"))
+                .contains("leaves the workspace")
+        );
+        assert!(
+            blocked_reason(run(&mut policy, "git push origin main"))
+                .contains("denied_arg_patterns")
+        );
+        assert!(
+            blocked_reason(run(&mut policy, "cargo install ripgrep"))
+                .contains("denied_arg_patterns")
+        );
+    }
+
+    #[test]
+    fn test_required_capabilities_from_call() {
+        let caps = |tool: &str, risk, details: ActionDetails, args| {
+            required_capabilities(tool, risk, &details, &args)
+        };
+        assert_eq!(
+            caps(
+                "http_api",
+                RiskLevel::Network,
+                ActionDetails::Other {
+                    info: String::new()
+                },
+                json!({"url": "https://api.example.com/v1"})
+            ),
+            vec![Capability::Network {
+                host: "api.example.com".into()
+            }]
+        );
+        assert_eq!(
+            caps(
+                "file_write",
+                RiskLevel::Write,
+                ActionDetails::FileWrite {
+                    path: "src/a.rs".into(),
+                    size_bytes: 1
+                },
+                json!({})
+            ),
+            vec![write("src/a.rs")]
+        );
+        assert_eq!(
+            caps(
+                "git_commit",
+                RiskLevel::Write,
+                ActionDetails::GitOperation {
+                    operation: "commit".into()
+                },
+                json!({"message": "x"})
+            ),
+            vec![Capability::ToolWrite {
+                tool: "git_commit".into()
+            }]
+        );
+        assert!(
+            caps(
+                "file_read",
+                RiskLevel::ReadOnly,
+                ActionDetails::FileRead {
+                    path: "src/a.rs".into()
+                },
+                json!({"path": "src/a.rs"})
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_denial_is_structured() {
+        let denial = CapabilityDenial {
+            capability: Capability::Network {
+                host: "evil.example".into(),
+            },
+            reason: "host is not in [safety.paranoid] network_allowlist".into(),
+            remedy: "use an allowlisted host".into(),
+        };
+        let value: serde_json::Value = serde_json::from_str(&denial.to_string()).unwrap();
+        assert_eq!(value["blocked"], "network");
+        assert_eq!(value["target"], "evil.example");
+    }
+}
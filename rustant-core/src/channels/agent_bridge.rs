@@ -3,8 +3,13 @@
 //! Optionally integrates with [`PairingManager`] to enforce device pairing for DM channels.
 //! When a `PairingManager` is attached, only messages from paired device IDs are routed;
 //! unpaired senders receive the `default_agent` fallback.
+//!
+//! With a [`ThreadContext`] attached, envelopes built by
+//! [`ChannelAgentBridge::channel_message_to_envelope_with_history`] start with
+//! the conversation's recent history, so a reply in a thread knows what was
+//! said above it.
 
-use crate::channels::{ChannelMessage, ChannelType, ChannelUser};
+use crate::channels::{Channel, ChannelMessage, ChannelType, ChannelUser, ThreadContext};
 use crate::multi::messaging::{AgentEnvelope, AgentPayload};
 use crate::multi::routing::{AgentRouter, RouteRequest};
 use crate::pairing::PairingManager;
@@ -19,6 +24,7 @@ use uuid::Uuid;
 pub struct ChannelAgentBridge {
     router: AgentRouter,
     pairing: Option<PairingManager>,
+    thread_context: Option<ThreadContext>,
 }
 
 impl ChannelAgentBridge {
@@ -26,9 +32,17 @@ impl ChannelAgentBridge {
         Self {
             router,
             pairing: None,
+            thread_context: None,
         }
     }
 
+    /// Include conversation history in envelopes built with
+    /// [`channel_message_to_envelope_with_history`](Self::channel_message_to_envelope_with_history).
+    pub fn with_thread_context(mut self, context: ThreadContext) -> Self {
+        self.thread_context = Some(context);
+        self
+    }
+
     /// Attach a pairing manager to enforce device-pairing for DM routing.
     pub fn with_pairing(mut self, pairing: PairingManager) -> Self {
         self.pairing = Some(pairing);
@@ -97,6 +111,35 @@ impl ChannelAgentBridge {
         )
     }
 
+    /// Like [`channel_message_to_envelope`](Self::channel_message_to_envelope),
+    /// but the task description starts with the message's recent conversation
+    /// history, fetched through `channel`. Without a thread context, or when
+    /// the fetch fails, the envelope carries the message alone.
+    pub async fn channel_message_to_envelope_with_history(
+        &mut self,
+        channel: &dyn Channel,
+        msg: &ChannelMessage,
+        from: Uuid,
+        to: Uuid,
+    ) -> AgentEnvelope {
+        let mut envelope = Self::channel_message_to_envelope(msg, from, to);
+        let Some(context) = self.thread_context.as_mut() else {
+            return envelope;
+        };
+        match context.history_for(channel, msg).await {
+            Ok(Some(history)) => {
+                if let AgentPayload::TaskRequest { description, .. } = &mut envelope.payload {
+                    *description = format!("{}\n\n{}", history, description);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::warn!(channel = channel.name(), error = %e, "Failed to fetch conversation history");
+            }
+        }
+        envelope
+    }
+
     /// Extract a ChannelMessage from an AgentEnvelope (if it contains a TaskResult).
     pub fn envelope_to_channel_message(
         envelope: &AgentEnvelope,
@@ -228,6 +271,108 @@ mod tests {
         assert!(msg.is_none());
     }
 
+    // -- Thread context --------------------------------------------------------
+
+    struct ThreadedChannel {
+        thread: Vec<ChannelMessage>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for ThreadedChannel {
+        fn name(&self) -> &str {
+            "slack"
+        }
+        fn channel_type(&self) -> ChannelType {
+            ChannelType::Slack
+        }
+        async fn connect(&mut self) -> Result<(), crate::error::RustantError> {
+            Ok(())
+        }
+        async fn disconnect(&mut self) -> Result<(), crate::error::RustantError> {
+            Ok(())
+        }
+        async fn send_message(
+            &self,
+            _msg: ChannelMessage,
+        ) -> Result<crate::channels::MessageId, crate::error::RustantError> {
+            Ok(crate::channels::MessageId::random())
+        }
+        async fn receive_messages(
+            &self,
+        ) -> Result<Vec<ChannelMessage>, crate::error::RustantError> {
+            Ok(Vec::new())
+        }
+        fn status(&self) -> crate::channels::ChannelStatus {
+            crate::channels::ChannelStatus::Connected
+        }
+        fn capabilities(&self) -> crate::channels::ChannelCapabilities {
+            crate::channels::ChannelCapabilities {
+                supports_threads: true,
+                ..Default::default()
+            }
+        }
+        async fn thread_history(
+            &self,
+            _channel_id: &str,
+            _thread_id: &crate::channels::ThreadId,
+            _after: Option<&crate::channels::MessageId>,
+            _limit: usize,
+        ) -> Result<Vec<ChannelMessage>, crate::error::RustantError> {
+            Ok(self.thread.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bridge_envelope_includes_thread_history() {
+        use crate::channels::ThreadId;
+        use crate::channels::thread_context::{HISTORY_END, HISTORY_START};
+
+        let earlier = ChannelMessage::text(
+            ChannelType::Slack,
+            "C1",
+            ChannelUser::new("U1", ChannelType::Slack).with_name("Alice"),
+            "The nightly build broke after the upgrade",
+        );
+        let channel = ThreadedChannel {
+            thread: vec![earlier],
+        };
+        let mention = ChannelMessage::text(
+            ChannelType::Slack,
+            "C1",
+            ChannelUser::new("U2", ChannelType::Slack),
+            "@rustant can you look?",
+        )
+        .with_thread(ThreadId::new("t1"));
+        let (from, to) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let mut plain = ChannelAgentBridge::new(AgentRouter::new());
+        let envelope = plain
+            .channel_message_to_envelope_with_history(&channel, &mention, from, to)
+            .await;
+        match &envelope.payload {
+            AgentPayload::TaskRequest { description, .. } => {
+                assert_eq!(description, "@rustant can you look?");
+            }
+            _ => panic!("Expected TaskRequest"),
+        }
+
+        let mut bridge = ChannelAgentBridge::new(AgentRouter::new())
+            .with_thread_context(ThreadContext::new(HashMap::new()));
+        let envelope = bridge
+            .channel_message_to_envelope_with_history(&channel, &mention, from, to)
+            .await;
+        match &envelope.payload {
+            AgentPayload::TaskRequest { description, .. } => {
+                assert!(description.starts_with(HISTORY_START));
+                assert!(description.contains("Alice: The nightly build broke after the upgrade"));
+                assert!(
+                    description.ends_with(&format!("{}\n\n@rustant can you look?", HISTORY_END))
+                );
+            }
+            _ => panic!("Expected TaskRequest"),
+        }
+    }
+
     // -- Pairing integration --------------------------------------------------
 
     #[test]
@@ -0,0 +1,902 @@
+//! Change Data Capture (CDC) for channel message processing.
+//!
+//! Provides stateful polling with cursor-based tracking, reply-chain detection,
+//! and a background polling loop that feeds the classification -> auto-reply pipeline.
+//! Configured [`sources`] extend the same cursor tracking to folders, SQL
+//! queries and HTTP endpoints, mapping their records into facts and inbox items.
+
+pub mod sources;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::style_tracker::CommunicationStyleTracker;
+use crate::memory::Fact;
+use crate::people::{PersonGraph, PersonObservation};
+pub use sources::{
+    CdcRecord, CdcSource, CdcSourceConfig, CdcSourceKind, CdcTarget, FieldMapping, SnapshotMode,
+    SourceBatch, SqlConnection, SqlDriver,
+};
+
+/// Pending errors kept per source.
+const MAX_SOURCE_ERRORS: usize = 10;
+
+/// Per-channel cursor state for tracking which messages have been processed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CdcState {
+    /// Per-channel cursors (channel_name -> cursor string).
+    pub cursors: HashMap<String, String>,
+    /// Message IDs we've sent (for reply-chain detection).
+    /// Maps channel -> Vec<SentMessageRecord>.
+    pub sent_messages: HashMap<String, Vec<SentMessageRecord>>,
+    /// Per-source sync state for configured data sources.
+    #[serde(default)]
+    pub sources: HashMap<String, SourceSyncState>,
+}
+
+/// Sync progress of one configured data source.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceSyncState {
+    /// Cursor to resume after.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// When the source was last polled, successfully or not.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_sync: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the source was last polled successfully.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<chrono::DateTime<chrono::Utc>>,
+    /// Records turned into actions since the source was added.
+    #[serde(default)]
+    pub records_processed: u64,
+    /// Errors since the last successful sync, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<SourceError>,
+    /// Whether the initial snapshot has been taken.
+    #[serde(default)]
+    pub snapshot_done: bool,
+    /// Record key to content fingerprint, used to skip unchanged records.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub fingerprints: HashMap<String, u64>,
+}
+
+/// A failed sync of a data source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceError {
+    pub at: chrono::DateTime<chrono::Utc>,
+    pub message: String,
+}
+
+impl SourceSyncState {
+    /// Whether the source should be polled now.
+    pub fn is_due(&self, interval_secs: u64, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.last_sync.is_none_or(|last| {
+            now - last >= chrono::Duration::seconds(interval_secs.min(i64::MAX as u64) as i64)
+        })
+    }
+
+    fn record_error(&mut self, message: String) {
+        self.errors.push(SourceError {
+            at: chrono::Utc::now(),
+            message,
+        });
+        if self.errors.len() > MAX_SOURCE_ERRORS {
+            self.errors.remove(0);
+        }
+    }
+}
+
+/// Record of a message sent by the agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentMessageRecord {
+    pub message_id: String,
+    pub channel: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl CdcState {
+    /// Load state from disk.
+    pub fn load(workspace: &Path) -> Self {
+        let path = workspace.join(".rustant").join("cdc").join("state.json");
+        if path.exists() {
+            std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Persist state to disk (atomic write).
+    pub fn save(&self, workspace: &Path) -> Result<(), String> {
+        let dir = workspace.join(".rustant").join("cdc");
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Create CDC dir: {}", e))?;
+        let path = dir.join("state.json");
+        let tmp = path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Serialize CDC state: {}", e))?;
+        std::fs::write(&tmp, &json).map_err(|e| format!("Write CDC state: {}", e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| format!("Rename CDC state: {}", e))?;
+        Ok(())
+    }
+
+    /// Get the cursor for a specific channel.
+    pub fn cursor_for(&self, channel: &str) -> Option<&str> {
+        self.cursors.get(channel).map(|s| s.as_str())
+    }
+
+    /// Update the cursor for a channel.
+    pub fn set_cursor(&mut self, channel: &str, cursor: String) {
+        self.cursors.insert(channel.to_string(), cursor);
+    }
+
+    /// Record a sent message for reply-chain detection.
+    pub fn record_sent(&mut self, channel: &str, message_id: &str) {
+        let records = self.sent_messages.entry(channel.to_string()).or_default();
+        records.push(SentMessageRecord {
+            message_id: message_id.to_string(),
+            channel: channel.to_string(),
+            timestamp: chrono::Utc::now(),
+        });
+    }
+
+    /// Check if a message is a reply to one of our sent messages.
+    pub fn is_reply_to_us(&self, channel: &str, reply_to: &str) -> bool {
+        self.sent_messages
+            .get(channel)
+            .map(|records| records.iter().any(|r| r.message_id == reply_to))
+            .unwrap_or(false)
+    }
+
+    /// Expire sent message records older than `ttl_days`.
+    pub fn expire_sent_records(&mut self, ttl_days: u64) {
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(ttl_days as i64);
+        for records in self.sent_messages.values_mut() {
+            records.retain(|r| r.timestamp > cutoff);
+        }
+        // Remove empty channels
+        self.sent_messages.retain(|_, v| !v.is_empty());
+    }
+}
+
+/// Action emitted by the CDC processor for the REPL/TUI to handle.
+#[derive(Debug, Clone)]
+pub enum CdcAction {
+    /// Auto-reply ready to send (channel, message_text, reply_to_id).
+    Reply {
+        channel: String,
+        text: String,
+        reply_to: Option<String>,
+    },
+    /// Message requires user attention (escalation).
+    Escalate {
+        channel: String,
+        sender: String,
+        summary: String,
+    },
+    /// Message added to digest for later review.
+    AddToDigest {
+        channel: String,
+        sender: String,
+        preview: String,
+    },
+    /// Status update for display.
+    StatusUpdate(String),
+    /// Create or update the fact a source record maps to.
+    UpsertFact {
+        source: String,
+        key: String,
+        content: String,
+        tags: Vec<String>,
+    },
+    /// Create or update the inbox item a source record maps to.
+    UpsertInboxItem {
+        source: String,
+        key: String,
+        text: String,
+        tags: Vec<String>,
+    },
+}
+
+/// Configuration for the CDC polling system.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CdcConfig {
+    /// Whether CDC polling is enabled.
+    pub enabled: bool,
+    /// Default polling interval in seconds.
+    pub default_interval_secs: u64,
+    /// Per-channel polling interval overrides.
+    #[serde(default)]
+    pub channel_intervals: HashMap<String, u64>,
+    /// Per-channel enable/disable.
+    #[serde(default)]
+    pub channel_enabled: HashMap<String, bool>,
+    /// How long to keep sent message records (days).
+    pub sent_record_ttl_days: u64,
+    /// Number of messages before generating style facts.
+    pub style_fact_threshold: usize,
+    /// Data sources synced into facts or inbox items.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<CdcSourceConfig>,
+    /// Named SQL connections used by SQL sources.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub connections: HashMap<String, SqlConnection>,
+}
+
+impl Default for CdcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_interval_secs: 60,
+            channel_intervals: HashMap::new(),
+            channel_enabled: HashMap::new(),
+            sent_record_ttl_days: 7,
+            style_fact_threshold: 50,
+            sources: Vec::new(),
+            connections: HashMap::new(),
+        }
+    }
+}
+
+impl CdcConfig {
+    /// Get the polling interval for a specific channel.
+    pub fn interval_for(&self, channel: &str) -> u64 {
+        self.channel_intervals
+            .get(channel)
+            .copied()
+            .unwrap_or(self.default_interval_secs)
+    }
+
+    /// Check if a specific channel is enabled for CDC.
+    pub fn is_channel_enabled(&self, channel: &str) -> bool {
+        self.channel_enabled.get(channel).copied().unwrap_or(true) // enabled by default
+    }
+
+    /// Look up a configured data source by name.
+    pub fn source(&self, name: &str) -> Option<&CdcSourceConfig> {
+        self.sources.iter().find(|s| s.name == name)
+    }
+}
+
+/// Result of syncing one data source.
+#[derive(Debug, Clone)]
+pub struct SourceSyncOutcome {
+    pub source: String,
+    /// Records that were new or changed.
+    pub records: usize,
+    /// Records read but unchanged since the last sync.
+    pub unchanged: usize,
+    /// Whether this sync only recorded the snapshot position.
+    pub snapshot_skipped: bool,
+    pub error: Option<String>,
+}
+
+/// The CDC processor that coordinates polling, classification, and action emission.
+pub struct CdcProcessor {
+    pub config: CdcConfig,
+    pub state: CdcState,
+    pub style_tracker: CommunicationStyleTracker,
+    /// Senders resolved to people, with their interaction timelines.
+    pub people: PersonGraph,
+    workspace: PathBuf,
+}
+
+impl CdcProcessor {
+    /// Create a new CDC processor.
+    pub fn new(config: CdcConfig, workspace: PathBuf) -> Self {
+        let state = CdcState::load(&workspace);
+        let style_tracker = CommunicationStyleTracker::new(config.style_fact_threshold);
+        let people = PersonGraph::load(&workspace);
+        Self {
+            config,
+            state,
+            style_tracker,
+            people,
+            workspace,
+        }
+    }
+
+    /// Process a batch of new messages from a channel.
+    ///
+    /// Returns CDC actions and any style facts generated.
+    pub fn process_messages(
+        &mut self,
+        channel: &str,
+        messages: &[(String, String, String, Option<String>)], // (id, sender, text, reply_to)
+    ) -> (Vec<CdcAction>, Vec<String>) {
+        let mut actions = Vec::new();
+        let mut facts = Vec::new();
+
+        for (msg_id, sender, text, reply_to) in messages {
+            // Track communication style
+            let style_facts = self.style_tracker.track_message(sender, channel, text);
+            facts.extend(style_facts);
+
+            // Check if this is a reply to one of our messages
+            let is_reply_to_us = reply_to
+                .as_ref()
+                .map(|rt| self.state.is_reply_to_us(channel, rt))
+                .unwrap_or(false);
+            let is_question = looks_like_question(text);
+
+            // Add to the sender's timeline in the person graph
+            let person = self.people.record_incoming(
+                PersonObservation::from_handle(channel, sender.as_str()),
+                chrono::Utc::now(),
+                text,
+                is_question.then(|| "question".to_string()),
+                is_question || is_reply_to_us,
+            );
+            self.style_tracker.link_person(sender, person);
+
+            // Simple heuristic classification
+            if is_reply_to_us {
+                // Replies to us get escalated for attention
+                actions.push(CdcAction::Escalate {
+                    channel: channel.to_string(),
+                    sender: sender.clone(),
+                    summary: truncate(text, 100),
+                });
+            } else if is_question {
+                // Questions might need auto-reply
+                actions.push(CdcAction::Reply {
+                    channel: channel.to_string(),
+                    text: "Received your question. Processing...".to_string(),
+                    reply_to: Some(msg_id.clone()),
+                });
+            } else {
+                // Other messages go to digest
+                actions.push(CdcAction::AddToDigest {
+                    channel: channel.to_string(),
+                    sender: sender.clone(),
+                    preview: truncate(text, 80),
+                });
+            }
+        }
+
+        // Update cursor to the last message ID
+        if let Some((last_id, _, _, _)) = messages.last() {
+            self.state.set_cursor(channel, last_id.clone());
+        }
+
+        // Expire old sent records
+        self.state
+            .expire_sent_records(self.config.sent_record_ttl_days);
+
+        // Persist state
+        if let Err(e) = self.state.save(&self.workspace) {
+            tracing::warn!("Failed to save CDC state: {}", e);
+        }
+        if !messages.is_empty()
+            && let Err(e) = self.people.save(&self.workspace)
+        {
+            tracing::warn!("Failed to save person graph: {}", e);
+        }
+
+        (actions, facts)
+    }
+
+    /// Record that we sent a message (for reply-chain detection).
+    pub fn record_sent_message(&mut self, channel: &str, message_id: &str) {
+        self.state.record_sent(channel, message_id);
+        let _ = self.state.save(&self.workspace);
+    }
+
+    /// Sync configured data sources and return the actions their records map to.
+    ///
+    /// Only sources that are enabled and due are polled, unless `force` is
+    /// set; `only` restricts the sync to one source. A failing source records
+    /// its error and keeps its cursor without affecting the others, and state
+    /// is saved after every source so a crash loses at most one batch.
+    pub async fn sync_sources(
+        &mut self,
+        force: bool,
+        only: Option<&str>,
+    ) -> (Vec<CdcAction>, Vec<SourceSyncOutcome>) {
+        let mut actions = Vec::new();
+        let mut outcomes = Vec::new();
+        let now = chrono::Utc::now();
+        let configs: Vec<CdcSourceConfig> = self
+            .config
+            .sources
+            .iter()
+            .filter(|s| only.is_none_or(|name| s.name == name))
+            .filter(|s| only.is_some() || s.enabled)
+            .cloned()
+            .collect();
+
+        for config in configs {
+            let state = self.state.sources.entry(config.name.clone()).or_default();
+            if !force && !state.is_due(config.interval_secs, now) {
+                continue;
+            }
+            let result =
+                match sources::build_source(&config, &self.config.connections, &self.workspace) {
+                    Ok(source) => source.poll(state.cursor.as_deref()).await,
+                    Err(e) => Err(e),
+                };
+
+            let mut outcome = SourceSyncOutcome {
+                source: config.name.clone(),
+                records: 0,
+                unchanged: 0,
+                snapshot_skipped: false,
+                error: None,
+            };
+            let state = self.state.sources.entry(config.name.clone()).or_default();
+            state.last_sync = Some(now);
+            match result {
+                Ok(batch) => {
+                    let skip = !state.snapshot_done && config.snapshot == SnapshotMode::Skip;
+                    for record in &batch.records {
+                        let key = config.mapping.key_for(record);
+                        let print = sources::fingerprint(record);
+                        if state.fingerprints.insert(key.clone(), print) == Some(print) {
+                            outcome.unchanged += 1;
+                            continue;
+                        }
+                        if skip {
+                            continue;
+                        }
+                        outcome.records += 1;
+                        actions.push(map_record(&config, key, record));
+                    }
+                    if let Some(cursor) = batch.cursor {
+                        state.cursor = Some(cursor);
+                    }
+                    outcome.snapshot_skipped = skip;
+                    state.snapshot_done = true;
+                    state.records_processed += outcome.records as u64;
+                    state.last_success = Some(now);
+                    state.errors.clear();
+                }
+                Err(e) => {
+                    tracing::warn!(source = %config.name, error = %e, "CDC source sync failed");
+                    state.record_error(e.clone());
+                    outcome.error = Some(e);
+                }
+            }
+            outcomes.push(outcome);
+
+            if let Err(e) = self.state.save(&self.workspace) {
+                tracing::warn!("Failed to save CDC state: {}", e);
+            }
+        }
+        (actions, outcomes)
+    }
+
+    /// Get the current CDC state summary for display.
+    pub fn status_summary(&self) -> String {
+        let mut output = String::from("CDC Status:\n");
+        output.push_str(&format!("  Enabled: {}\n", self.config.enabled));
+        output.push_str(&format!(
+            "  Default interval: {}s\n",
+            self.config.default_interval_secs
+        ));
+        output.push_str(&format!(
+            "  Channels with cursors: {}\n",
+            self.state.cursors.len()
+        ));
+        for (ch, cursor) in &self.state.cursors {
+            output.push_str(&format!("    {} -> {}\n", ch, cursor));
+        }
+        output.push_str(&format!(
+            "  Style profiles tracked: {}\n",
+            self.style_tracker.profiles.len()
+        ));
+        output.push_str(&format!(
+            "  Total messages processed: {}\n",
+            self.style_tracker.total_messages
+        ));
+        if !self.config.sources.is_empty() {
+            output.push_str(&format!("  Sources: {}\n", self.config.sources.len()));
+            for source in &self.config.sources {
+                let state = self.state.sources.get(&source.name);
+                output.push_str(&format!(
+                    "    {} -> {} records, {} pending errors\n",
+                    source.name,
+                    state.map_or(0, |s| s.records_processed),
+                    state.map_or(0, |s| s.errors.len())
+                ));
+            }
+        }
+        output
+    }
+}
+
+/// Map a source record into the action for its target.
+fn map_record(config: &CdcSourceConfig, key: String, record: &CdcRecord) -> CdcAction {
+    let text = config.mapping.render(record);
+    let tags = config.mapping.tags_for(record);
+    match config.target {
+        CdcTarget::Fact => CdcAction::UpsertFact {
+            source: config.name.clone(),
+            key,
+            content: text,
+            tags,
+        },
+        CdcTarget::Inbox => CdcAction::UpsertInboxItem {
+            source: config.name.clone(),
+            key,
+            text,
+            tags,
+        },
+    }
+}
+
+/// Facts synced from CDC sources, persisted in `.rustant/cdc/facts.json`.
+///
+/// Each fact's `source` is `cdc:<source>/<key>`, so a changed record updates
+/// its fact in place. The agent loads these facts into long-term memory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CdcFacts {
+    pub facts: Vec<Fact>,
+}
+
+impl CdcFacts {
+    fn path(workspace: &Path) -> PathBuf {
+        workspace.join(".rustant").join("cdc").join("facts.json")
+    }
+
+    /// Load synced facts from disk.
+    pub fn load(workspace: &Path) -> Self {
+        std::fs::read_to_string(Self::path(workspace))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist synced facts to disk (atomic write).
+    pub fn save(&self, workspace: &Path) -> Result<(), String> {
+        let path = Self::path(workspace);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Create CDC dir: {}", e))?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Serialize CDC facts: {}", e))?;
+        std::fs::write(&tmp, &json).map_err(|e| format!("Write CDC facts: {}", e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| format!("Rename CDC facts: {}", e))?;
+        Ok(())
+    }
+
+    /// Create or update the fact for a source record. Returns `true` if it
+    /// was created.
+    pub fn upsert(&mut self, source: &str, key: &str, content: &str, tags: &[String]) -> bool {
+        let origin = format!("cdc:{}/{}", source, key);
+        if let Some(fact) = self.facts.iter_mut().find(|f| f.source == origin) {
+            fact.content = content.to_string();
+            fact.tags = tags.to_vec();
+            fact.created_at = chrono::Utc::now();
+            return false;
+        }
+        self.facts
+            .push(Fact::new(content, origin).with_tags(tags.to_vec()));
+        true
+    }
+}
+
+/// Simple heuristic: does this message look like a question?
+fn looks_like_question(text: &str) -> bool {
+    text.trim().ends_with('?')
+        || text.to_lowercase().starts_with("can ")
+        || text.to_lowercase().starts_with("could ")
+        || text.to_lowercase().starts_with("how ")
+        || text.to_lowercase().starts_with("what ")
+        || text.to_lowercase().starts_with("when ")
+        || text.to_lowercase().starts_with("where ")
+        || text.to_lowercase().starts_with("why ")
+        || text.to_lowercase().starts_with("is ")
+        || text.to_lowercase().starts_with("are ")
+        || text.to_lowercase().starts_with("do ")
+        || text.to_lowercase().starts_with("does ")
+}
+
+/// Truncate a string to max length with "...".
+fn truncate(s: &str, max: usize) -> String {
+    if s.len() <= max {
+        s.to_string()
+    } else {
+        format!("{}...", &s[..max])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cdc_state_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+
+        let mut state = CdcState::default();
+        state.set_cursor("slack", "123.456".into());
+        state.record_sent("slack", "789.012");
+        state.save(&workspace).unwrap();
+
+        let loaded = CdcState::load(&workspace);
+        assert_eq!(loaded.cursor_for("slack"), Some("123.456"));
+        assert!(loaded.is_reply_to_us("slack", "789.012"));
+    }
+
+    #[test]
+    fn test_cdc_config_defaults() {
+        let config = CdcConfig::default();
+        assert!(config.enabled);
+        assert_eq!(config.default_interval_secs, 60);
+        assert_eq!(config.interval_for("slack"), 60);
+        assert!(config.is_channel_enabled("slack"));
+    }
+
+    #[test]
+    fn test_cdc_config_channel_overrides() {
+        let mut config = CdcConfig::default();
+        config.channel_intervals.insert("slack".into(), 120);
+        config.channel_enabled.insert("irc".into(), false);
+
+        assert_eq!(config.interval_for("slack"), 120);
+        assert_eq!(config.interval_for("email"), 60); // default
+        assert!(!config.is_channel_enabled("irc"));
+        assert!(config.is_channel_enabled("slack"));
+    }
+
+    #[test]
+    fn test_cdc_processor_process_messages() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let config = CdcConfig {
+            style_fact_threshold: 50,
+            ..Default::default()
+        };
+        let mut processor = CdcProcessor::new(config, workspace);
+
+        let messages = vec![
+            (
+                "1".into(),
+                "user1".into(),
+                "Can you help me with this?".into(),
+                None,
+            ),
+            (
+                "2".into(),
+                "user2".into(),
+                "Just an update on the project".into(),
+                None,
+            ),
+        ];
+
+        let (actions, _facts) = processor.process_messages("slack", &messages);
+        assert_eq!(actions.len(), 2);
+        // First is a question -> Reply
+        assert!(matches!(&actions[0], CdcAction::Reply { .. }));
+        // Second is not a question -> AddToDigest
+        assert!(matches!(&actions[1], CdcAction::AddToDigest { .. }));
+    }
+
+    #[test]
+    fn test_process_messages_links_senders_to_people() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let mut processor = CdcProcessor::new(CdcConfig::default(), workspace.clone());
+
+        let messages = vec![
+            (
+                "1".into(),
+                "U1".into(),
+                "Can you send the report?".into(),
+                None,
+            ),
+            ("2".into(), "U1".into(), "Thanks".into(), None),
+        ];
+        processor.process_messages("slack", &messages);
+
+        let person = processor.style_tracker.get_profile("U1").unwrap().person_id;
+        let graph = PersonGraph::load(&workspace);
+        let linked = graph.get(person.unwrap()).unwrap();
+        assert_eq!(linked.interactions.len(), 2);
+        assert!(linked.awaiting_reply().is_some());
+        assert_eq!(
+            processor.style_tracker.profiles_for_person(linked.id).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_reply_chain_detection() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let config = CdcConfig::default();
+        let mut processor = CdcProcessor::new(config, workspace);
+
+        // Record that we sent a message
+        processor.record_sent_message("slack", "our_msg_123");
+
+        // Process a reply to our message
+        let messages = vec![(
+            "reply_1".into(),
+            "user1".into(),
+            "Thanks for that info!".into(),
+            Some("our_msg_123".into()),
+        )];
+
+        let (actions, _) = processor.process_messages("slack", &messages);
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(&actions[0], CdcAction::Escalate { .. }));
+    }
+
+    #[test]
+    fn test_sent_record_expiry() {
+        let mut state = CdcState::default();
+        state.record_sent("slack", "old_msg");
+
+        // Manually set timestamp to 10 days ago
+        if let Some(records) = state.sent_messages.get_mut("slack") {
+            records[0].timestamp = chrono::Utc::now() - chrono::Duration::days(10);
+        }
+
+        state.expire_sent_records(7);
+        assert!(!state.is_reply_to_us("slack", "old_msg"));
+    }
+
+    #[test]
+    fn test_looks_like_question() {
+        assert!(looks_like_question("How do I do this?"));
+        assert!(looks_like_question("Can you help me"));
+        assert!(looks_like_question("What is the status?"));
+        assert!(!looks_like_question("Just an update"));
+        assert!(!looks_like_question("Thanks for the info"));
+    }
+
+    #[test]
+    fn test_status_summary() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let config = CdcConfig::default();
+        let processor = CdcProcessor::new(config, workspace);
+
+        let summary = processor.status_summary();
+        assert!(summary.contains("CDC Status"));
+        assert!(summary.contains("Enabled: true"));
+    }
+
+    fn folder_source(name: &str, path: &str, target: CdcTarget) -> CdcSourceConfig {
+        CdcSourceConfig {
+            name: name.into(),
+            kind: CdcSourceKind::Folder {
+                path: path.into(),
+                extensions: Vec::new(),
+            },
+            interval_secs: 300,
+            snapshot: SnapshotMode::Full,
+            target,
+            mapping: FieldMapping {
+                key: Some("id".into()),
+                template: Some("Ticket {id}: {subject}".into()),
+                tags: vec!["support".into()],
+                tag_fields: Vec::new(),
+            },
+            batch_size: 500,
+            enabled: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_sources_snapshot_then_incremental() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(workspace.join("drop")).unwrap();
+        std::fs::write(
+            workspace.join("drop/a.csv"),
+            "id,subject\n1,Login\n2,Billing\n",
+        )
+        .unwrap();
+        let config = CdcConfig {
+            sources: vec![folder_source("tickets", "drop", CdcTarget::Inbox)],
+            ..Default::default()
+        };
+        let mut processor = CdcProcessor::new(config, workspace.clone());
+
+        let (actions, outcomes) = processor.sync_sources(false, None).await;
+        assert_eq!(actions.len(), 2);
+        assert!(matches!(
+            &actions[0],
+            CdcAction::UpsertInboxItem { key, text, .. } if key == "1" && text == "Ticket 1: Login"
+        ));
+        assert_eq!(outcomes[0].records, 2);
+
+        // Not due yet, so nothing is polled.
+        let (actions, outcomes) = processor.sync_sources(false, None).await;
+        assert!(actions.is_empty() && outcomes.is_empty());
+
+        // A rewritten file yields only the changed row.
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        std::fs::write(
+            workspace.join("drop/a.csv"),
+            "id,subject\n1,Login\n2,Billing (refund)\n",
+        )
+        .unwrap();
+        let (actions, outcomes) = processor.sync_sources(true, None).await;
+        assert_eq!(actions.len(), 1);
+        assert_eq!(outcomes[0].unchanged, 1);
+
+        let state = CdcState::load(&workspace);
+        assert_eq!(state.sources["tickets"].records_processed, 3);
+        assert!(state.sources["tickets"].cursor.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_sync_sources_skip_snapshot() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(workspace.join("drop")).unwrap();
+        std::fs::write(workspace.join("drop/a.csv"), "id,subject\n1,Old\n").unwrap();
+        let mut source = folder_source("tickets", "drop", CdcTarget::Fact);
+        source.snapshot = SnapshotMode::Skip;
+        let config = CdcConfig {
+            sources: vec![source],
+            ..Default::default()
+        };
+        let mut processor = CdcProcessor::new(config, workspace.clone());
+
+        let (actions, outcomes) = processor.sync_sources(true, None).await;
+        assert!(actions.is_empty());
+        assert!(outcomes[0].snapshot_skipped);
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        std::fs::write(workspace.join("drop/b.csv"), "id,subject\n2,New\n").unwrap();
+        let (actions, _) = processor.sync_sources(true, None).await;
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(&actions[0], CdcAction::UpsertFact { key, .. } if key == "2"));
+    }
+
+    #[tokio::test]
+    async fn test_sync_sources_isolates_failures() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(workspace.join("drop")).unwrap();
+        std::fs::write(workspace.join("drop/a.csv"), "id,subject\n1,Login\n").unwrap();
+        let broken = CdcSourceConfig {
+            name: "orders".into(),
+            kind: CdcSourceKind::Sql {
+                connection: "missing".into(),
+                query: "SELECT * FROM orders".into(),
+                cursor_column: "updated_at".into(),
+            },
+            ..folder_source("orders", "drop", CdcTarget::Fact)
+        };
+        let config = CdcConfig {
+            sources: vec![broken, folder_source("tickets", "drop", CdcTarget::Fact)],
+            ..Default::default()
+        };
+        let mut processor = CdcProcessor::new(config, workspace.clone());
+
+        let (actions, outcomes) = processor.sync_sources(true, None).await;
+        assert_eq!(actions.len(), 1);
+        assert!(outcomes[0].error.as_deref().unwrap().contains("missing"));
+        assert!(outcomes[1].error.is_none());
+
+        let state = CdcState::load(&workspace);
+        assert_eq!(state.sources["orders"].errors.len(), 1);
+        assert!(state.sources["orders"].last_success.is_none());
+        assert!(state.sources["tickets"].errors.is_empty());
+    }
+
+    #[test]
+    fn test_cdc_facts_upsert_in_place() {
+        let dir = TempDir::new().unwrap();
+        let mut facts = CdcFacts::default();
+        assert!(facts.upsert("tickets", "1", "Ticket 1: open", &[]));
+        assert!(!facts.upsert("tickets", "1", "Ticket 1: closed", &[]));
+        facts.save(dir.path()).unwrap();
+
+        let loaded = CdcFacts::load(dir.path());
+        assert_eq!(loaded.facts.len(), 1);
+        assert_eq!(loaded.facts[0].content, "Ticket 1: closed");
+        assert_eq!(loaded.facts[0].source, "cdc:tickets/1");
+    }
+}
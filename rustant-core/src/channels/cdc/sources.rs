@@ -0,0 +1,816 @@
+//! Pluggable CDC data sources.
+//!
+//! A source turns an external system into batches of [`CdcRecord`]s after a
+//! cursor: a watched folder (new or changed files, parsed by type), a SQL
+//! query with an `updated_at`-style cursor column, or a JSON HTTP endpoint
+//! with cursor pagination. Records are mapped into [`CdcAction`]s that
+//! upsert facts or inbox items.
+//!
+//! [`CdcAction`]: super::CdcAction
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// A record read from a source: field name to JSON value.
+pub type CdcRecord = Map<String, Value>;
+
+/// Records returned by one poll, and the cursor to resume after them.
+#[derive(Debug, Clone, Default)]
+pub struct SourceBatch {
+    pub records: Vec<CdcRecord>,
+    /// Cursor after this batch; `None` keeps the previous cursor.
+    pub cursor: Option<String>,
+}
+
+/// A CDC data source.
+#[async_trait]
+pub trait CdcSource: Send + Sync {
+    /// Short kind name shown in status output (`folder`, `sql`, `http`).
+    fn kind(&self) -> &str;
+
+    /// Read records changed after `cursor`, or everything when it is `None`.
+    async fn poll(&self, cursor: Option<&str>) -> Result<SourceBatch, String>;
+}
+
+/// How the first sync of a source treats existing data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotMode {
+    /// Import everything that exists, then follow changes.
+    #[default]
+    Full,
+    /// Record the current cursor without importing, then follow changes.
+    Skip,
+}
+
+/// Where mapped records go.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CdcTarget {
+    /// Facts in the knowledge store, updated in place by key.
+    #[default]
+    Fact,
+    /// Items in the inbox, updated in place by key.
+    Inbox,
+}
+
+/// How record fields become a fact or inbox item.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldMapping {
+    /// Field holding a stable record key. Without one, folder records are
+    /// keyed by file (and row) and other records by their content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Text template with `{field}` placeholders. Defaults to
+    /// `field: value` pairs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Tags added to every item.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Fields whose values are added as tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tag_fields: Vec<String>,
+}
+
+impl FieldMapping {
+    /// Render a record into item text.
+    pub fn render(&self, record: &CdcRecord) -> String {
+        match &self.template {
+            Some(template) => render_template(template, record),
+            None => record
+                .iter()
+                .map(|(k, v)| format!("{}: {}", k, value_text(v)))
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+
+    /// Tags for a record: the static tags plus the `tag_fields` values.
+    pub fn tags_for(&self, record: &CdcRecord) -> Vec<String> {
+        let mut tags = self.tags.clone();
+        for field in &self.tag_fields {
+            if let Some(value) = record.get(field).map(value_text)
+                && !value.is_empty()
+                && !tags.contains(&value)
+            {
+                tags.push(value);
+            }
+        }
+        tags
+    }
+
+    /// The record's key, from the mapped field or a content hash.
+    pub fn key_for(&self, record: &CdcRecord) -> String {
+        self.key
+            .as_ref()
+            .and_then(|field| record.get(field))
+            .map(value_text)
+            .filter(|k| !k.is_empty())
+            .or_else(|| record.get(RECORD_KEY_FIELD).map(value_text))
+            .unwrap_or_else(|| format!("{:016x}", fingerprint(record)))
+    }
+}
+
+/// Field folder sources set to key records by file and row.
+pub const RECORD_KEY_FIELD: &str = "_key";
+
+/// A named SQL connection that sources refer to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SqlConnection {
+    pub driver: SqlDriver,
+    /// SQLite file path, or a `postgres://` connection URL.
+    pub url: String,
+    /// Environment variable holding the password, kept out of the URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_env: Option<String>,
+}
+
+/// Database drivers for SQL sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlDriver {
+    /// Read directly with the bundled SQLite library.
+    Sqlite,
+    /// Read through the `psql` client, which must be on `PATH`.
+    Postgres,
+}
+
+/// The kind-specific part of a source configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CdcSourceKind {
+    /// Files in a folder, relative to the workspace unless absolute.
+    Folder {
+        path: PathBuf,
+        /// Extensions to read; defaults to every supported type.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        extensions: Vec<String>,
+    },
+    /// Rows of a query, followed by an increasing cursor column.
+    Sql {
+        /// Name of an entry in `[cdc.connections]`.
+        connection: String,
+        query: String,
+        cursor_column: String,
+    },
+    /// A JSON endpoint that pages with a cursor query parameter.
+    Http {
+        url: String,
+        /// JSON pointer to the array of records; empty for a top-level array.
+        #[serde(default)]
+        items_pointer: String,
+        /// Query parameter the cursor is sent in.
+        #[serde(default = "default_cursor_param")]
+        cursor_param: String,
+        /// JSON pointer to the next page's cursor in the response.
+        #[serde(default)]
+        next_cursor_pointer: String,
+        /// Request headers; values may reference `${ENV_VAR}`.
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        headers: HashMap<String, String>,
+    },
+}
+
+fn default_cursor_param() -> String {
+    "cursor".into()
+}
+
+/// A configured CDC source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CdcSourceConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: CdcSourceKind,
+    /// Seconds between syncs.
+    #[serde(default = "default_source_interval")]
+    pub interval_secs: u64,
+    #[serde(default)]
+    pub snapshot: SnapshotMode,
+    #[serde(default)]
+    pub target: CdcTarget,
+    #[serde(default)]
+    pub mapping: FieldMapping,
+    /// Maximum records read per page or query.
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_source_interval() -> u64 {
+    300
+}
+
+fn default_batch_size() -> usize {
+    500
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Build the built-in source for a configuration.
+pub fn build_source(
+    config: &CdcSourceConfig,
+    connections: &HashMap<String, SqlConnection>,
+    workspace: &Path,
+) -> Result<Box<dyn CdcSource>, String> {
+    Ok(match &config.kind {
+        CdcSourceKind::Folder { path, extensions } => Box::new(FolderSource {
+            root: workspace.join(path),
+            extensions: extensions.iter().map(|e| e.to_lowercase()).collect(),
+        }),
+        CdcSourceKind::Sql {
+            connection,
+            query,
+            cursor_column,
+        } => {
+            let connection = connections
+                .get(connection)
+                .ok_or_else(|| format!("unknown SQL connection '{}'", connection))?;
+            Box::new(SqlSource {
+                connection: connection.clone(),
+                query: query.clone(),
+                cursor_column: cursor_column.clone(),
+                batch_size: config.batch_size,
+                workspace: workspace.to_path_buf(),
+            })
+        }
+        CdcSourceKind::Http {
+            url,
+            items_pointer,
+            cursor_param,
+            next_cursor_pointer,
+            headers,
+        } => Box::new(HttpSource {
+            url: url.clone(),
+            items_pointer: items_pointer.clone(),
+            cursor_param: cursor_param.clone(),
+            next_cursor_pointer: next_cursor_pointer.clone(),
+            headers: headers.clone(),
+        }),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Folder source
+// ---------------------------------------------------------------------------
+
+/// File types the folder source parses.
+const FOLDER_EXTENSIONS: &[&str] = &["csv", "json", "jsonl", "md", "txt"];
+
+/// Watches a folder for new and changed files.
+///
+/// The cursor is the newest modification time seen, in milliseconds since
+/// the epoch. CSV and JSONL files yield a record per row or line, JSON files
+/// a record per array element, and text files one record per file.
+pub struct FolderSource {
+    root: PathBuf,
+    extensions: Vec<String>,
+}
+
+#[async_trait]
+impl CdcSource for FolderSource {
+    fn kind(&self) -> &str {
+        "folder"
+    }
+
+    async fn poll(&self, cursor: Option<&str>) -> Result<SourceBatch, String> {
+        let since: u128 = cursor.and_then(|c| c.parse().ok()).unwrap_or(0);
+        let root = self.root.clone();
+        let extensions = self.extensions.clone();
+        tokio::task::spawn_blocking(move || scan_folder(&root, &extensions, since))
+            .await
+            .map_err(|e| format!("folder scan panicked: {}", e))?
+    }
+}
+
+fn scan_folder(root: &Path, extensions: &[String], since: u128) -> Result<SourceBatch, String> {
+    let entries =
+        std::fs::read_dir(root).map_err(|e| format!("cannot read {}: {}", root.display(), e))?;
+    let mut changed = Vec::new();
+    let mut newest = since;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(ext) = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+        else {
+            continue;
+        };
+        let wanted = if extensions.is_empty() {
+            FOLDER_EXTENSIONS.contains(&ext.as_str())
+        } else {
+            extensions.contains(&ext)
+        };
+        let modified = entry
+            .metadata()
+            .ok()
+            .filter(|m| m.is_file())
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis());
+        if let (true, Some(modified)) = (wanted, modified)
+            && modified > since
+        {
+            newest = newest.max(modified);
+            changed.push((modified, path, ext));
+        }
+    }
+    changed.sort();
+
+    let mut records = Vec::new();
+    for (_, path, ext) in changed {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let rows = parse_file(&ext, &text).map_err(|e| format!("{}: {}", name, e))?;
+        let single = rows.len() == 1;
+        for (i, mut row) in rows.into_iter().enumerate() {
+            let key = if single {
+                name.clone()
+            } else {
+                format!("{}#{}", name, i + 1)
+            };
+            row.entry(RECORD_KEY_FIELD).or_insert(Value::String(key));
+            row.entry("_file").or_insert(Value::String(name.clone()));
+            records.push(row);
+        }
+    }
+    Ok(SourceBatch {
+        records,
+        cursor: (newest > since).then(|| newest.to_string()),
+    })
+}
+
+/// Parse a file's text into records according to its extension.
+fn parse_file(ext: &str, text: &str) -> Result<Vec<CdcRecord>, String> {
+    match ext {
+        "csv" => Ok(parse_csv_records(text)),
+        "json" => match serde_json::from_str::<Value>(text).map_err(|e| e.to_string())? {
+            Value::Array(items) => Ok(items.into_iter().filter_map(into_record).collect()),
+            other => Ok(into_record(other).into_iter().collect()),
+        },
+        "jsonl" => text
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| {
+                serde_json::from_str::<Value>(l)
+                    .map_err(|e| e.to_string())
+                    .map(into_record)
+            })
+            .filter_map(Result::transpose)
+            .collect(),
+        _ => {
+            let mut record = Map::new();
+            let title = text
+                .lines()
+                .find(|l| !l.trim().is_empty())
+                .map(|l| l.trim_start_matches('#').trim().to_string())
+                .unwrap_or_default();
+            record.insert("title".into(), Value::String(title));
+            record.insert("content".into(), Value::String(text.to_string()));
+            Ok(vec![record])
+        }
+    }
+}
+
+fn into_record(value: Value) -> Option<CdcRecord> {
+    match value {
+        Value::Object(map) => Some(map),
+        _ => None,
+    }
+}
+
+/// Parse CSV text with a header row into records. Handles quoted fields,
+/// doubled quotes, and newlines inside quotes.
+pub fn parse_csv_records(text: &str) -> Vec<CdcRecord> {
+    let mut rows = parse_csv(text).into_iter();
+    let Some(header) = rows.next() else {
+        return Vec::new();
+    };
+    rows.filter(|row| row.iter().any(|cell| !cell.is_empty()))
+        .map(|row| {
+            header
+                .iter()
+                .zip(row.into_iter().chain(std::iter::repeat(String::new())))
+                .map(|(name, cell)| (name.trim().to_string(), Value::String(cell)))
+                .collect()
+        })
+        .collect()
+}
+
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => row.push(std::mem::take(&mut cell)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+    rows
+}
+
+// ---------------------------------------------------------------------------
+// SQL source
+// ---------------------------------------------------------------------------
+
+/// Reads rows of a query in cursor-column order.
+///
+/// The configured query is wrapped as a subquery, filtered to rows whose
+/// cursor column is greater than the stored cursor, and read in pages of
+/// `batch_size` until exhausted.
+pub struct SqlSource {
+    connection: SqlConnection,
+    query: String,
+    cursor_column: String,
+    batch_size: usize,
+    workspace: PathBuf,
+}
+
+impl SqlSource {
+    /// The paged query. SQLite binds the cursor as `?1`; for `psql` it is
+    /// inlined as an escaped literal.
+    fn page_sql(&self, cursor: Option<&str>) -> String {
+        let column = format!("\"{}\"", self.cursor_column.replace('"', "\"\""));
+        let filter = match (cursor, self.connection.driver) {
+            (None, _) => String::new(),
+            (Some(_), SqlDriver::Sqlite) => format!(" WHERE {} > ?1", column),
+            (Some(c), SqlDriver::Postgres) => {
+                format!(" WHERE {}::text > '{}'", column, c.replace('\'', "''"))
+            }
+        };
+        format!(
+            "SELECT * FROM ({}) AS cdc_source{} ORDER BY {} LIMIT {}",
+            self.query.trim().trim_end_matches(';'),
+            filter,
+            column,
+            self.batch_size
+        )
+    }
+
+    async fn fetch_page(&self, cursor: Option<&str>) -> Result<Vec<CdcRecord>, String> {
+        let sql = self.page_sql(cursor);
+        match self.connection.driver {
+            SqlDriver::Sqlite => {
+                let path = self.workspace.join(&self.connection.url);
+                let cursor = cursor.map(str::to_string);
+                tokio::task::spawn_blocking(move || query_sqlite(&path, &sql, cursor.as_deref()))
+                    .await
+                    .map_err(|e| format!("SQLite query panicked: {}", e))?
+            }
+            SqlDriver::Postgres => {
+                let mut cmd = tokio::process::Command::new("psql");
+                cmd.arg(&self.connection.url)
+                    .args(["--csv", "--no-psqlrc", "-v", "ON_ERROR_STOP=1", "-c"])
+                    .arg(&sql);
+                if let Some(var) = &self.connection.password_env {
+                    let password = std::env::var(var)
+                        .map_err(|_| format!("password variable {} is not set", var))?;
+                    cmd.env("PGPASSWORD", password);
+                }
+                let output = cmd
+                    .output()
+                    .await
+                    .map_err(|e| format!("cannot run psql: {}", e))?;
+                if !output.status.success() {
+                    return Err(format!(
+                        "psql failed: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    ));
+                }
+                Ok(parse_csv_records(&String::from_utf8_lossy(&output.stdout)))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl CdcSource for SqlSource {
+    fn kind(&self) -> &str {
+        "sql"
+    }
+
+    async fn poll(&self, cursor: Option<&str>) -> Result<SourceBatch, String> {
+        let mut batch = SourceBatch::default();
+        let mut cursor = cursor.map(str::to_string);
+        loop {
+            let rows = self.fetch_page(cursor.as_deref()).await?;
+            let full_page = rows.len() >= self.batch_size;
+            if let Some(last) = rows.last() {
+                let next = last
+                    .get(&self.cursor_column)
+                    .map(value_text)
+                    .ok_or_else(|| {
+                        format!("query result has no '{}' column", self.cursor_column)
+                    })?;
+                cursor = Some(next.clone());
+                batch.cursor = Some(next);
+            }
+            batch.records.extend(rows);
+            if !full_page {
+                return Ok(batch);
+            }
+        }
+    }
+}
+
+fn query_sqlite(path: &Path, sql: &str, cursor: Option<&str>) -> Result<Vec<CdcRecord>, String> {
+    use rusqlite::types::ValueRef;
+
+    let conn =
+        rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format!("cannot open {}: {}", path.display(), e))?;
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let params: Vec<&dyn rusqlite::ToSql> = match &cursor {
+        Some(c) => vec![c as &dyn rusqlite::ToSql],
+        None => Vec::new(),
+    };
+    let mut rows = stmt.query(params.as_slice()).map_err(|e| e.to_string())?;
+    let mut records = Vec::new();
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let mut record = Map::new();
+        for (i, name) in columns.iter().enumerate() {
+            let value = match row.get_ref(i).map_err(|e| e.to_string())? {
+                ValueRef::Null => Value::Null,
+                ValueRef::Integer(n) => Value::from(n),
+                ValueRef::Real(f) => Value::from(f),
+                ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).into_owned()),
+                ValueRef::Blob(b) => Value::String(format!("<{} bytes>", b.len())),
+            };
+            record.insert(name.clone(), value);
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+// ---------------------------------------------------------------------------
+// HTTP source
+// ---------------------------------------------------------------------------
+
+/// Most pages read from an HTTP source in one sync.
+const MAX_HTTP_PAGES: usize = 50;
+
+/// Pages through a JSON endpoint with a cursor query parameter.
+///
+/// The stored cursor is the one the last page was requested with, so the
+/// next sync re-reads that page and picks up records appended to it;
+/// unchanged records are skipped by fingerprint.
+pub struct HttpSource {
+    url: String,
+    items_pointer: String,
+    cursor_param: String,
+    next_cursor_pointer: String,
+    headers: HashMap<String, String>,
+}
+
+#[async_trait]
+impl CdcSource for HttpSource {
+    fn kind(&self) -> &str {
+        "http"
+    }
+
+    async fn poll(&self, cursor: Option<&str>) -> Result<SourceBatch, String> {
+        let client = reqwest::Client::new();
+        let mut batch = SourceBatch::default();
+        let mut cursor = cursor.map(str::to_string);
+        for _ in 0..MAX_HTTP_PAGES {
+            let mut request = client.get(&self.url);
+            if let Some(c) = &cursor {
+                request = request.query(&[(self.cursor_param.as_str(), c.as_str())]);
+            }
+            for (name, value) in &self.headers {
+                request = request.header(name, expand_env(value));
+            }
+            let response = request.send().await.map_err(|e| e.to_string())?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(format!("{} returned {}", self.url, status));
+            }
+            let body: Value = response.json().await.map_err(|e| e.to_string())?;
+            let items = body
+                .pointer(&self.items_pointer)
+                .and_then(Value::as_array)
+                .ok_or_else(|| format!("no array at '{}' in response", self.items_pointer))?;
+            batch
+                .records
+                .extend(items.iter().cloned().filter_map(into_record));
+            batch.cursor = cursor.clone();
+
+            let next = if self.next_cursor_pointer.is_empty() {
+                None
+            } else {
+                body.pointer(&self.next_cursor_pointer)
+                    .filter(|v| !v.is_null())
+                    .map(value_text)
+                    .filter(|c| !c.is_empty())
+            };
+            match next {
+                Some(next) if Some(&next) != cursor.as_ref() => cursor = Some(next),
+                _ => break,
+            }
+        }
+        Ok(batch)
+    }
+}
+
+/// Replace `${VAR}` references with environment values.
+fn expand_env(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(&std::env::var(&rest[start + 2..start + end]).unwrap_or_default());
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// A value as plain text: strings unquoted, null as empty.
+pub fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Fill `{field}` placeholders from a record; unknown fields render empty.
+pub fn render_template(template: &str, record: &CdcRecord) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let field = &rest[start + 1..start + end];
+        out.push_str(&record.get(field).map(value_text).unwrap_or_default());
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Stable FNV-1a hash of a record, used to skip unchanged records.
+pub fn fingerprint(record: &CdcRecord) -> u64 {
+    let text = serde_json::to_string(record).unwrap_or_default();
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_csv_records() {
+        let text =
+            "id,subject,notes\n1,Login broken,\"says \"\"help\"\", twice\"\n2,\"Multi\nline\",\n";
+        let records = parse_csv_records(text);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["notes"], "says \"help\", twice");
+        assert_eq!(records[1]["subject"], "Multi\nline");
+        assert_eq!(records[1]["notes"], "");
+    }
+
+    #[test]
+    fn test_mapping_render_key_and_tags() {
+        let record: CdcRecord = serde_json::from_value(serde_json::json!({
+            "id": 42, "subject": "Refund", "status": "open"
+        }))
+        .unwrap();
+        let mapping = FieldMapping {
+            key: Some("id".into()),
+            template: Some("Ticket #{id}: {subject} ({status}){missing}".into()),
+            tags: vec!["support".into()],
+            tag_fields: vec!["status".into()],
+        };
+        assert_eq!(mapping.render(&record), "Ticket #42: Refund (open)");
+        assert_eq!(mapping.key_for(&record), "42");
+        assert_eq!(mapping.tags_for(&record), vec!["support", "open"]);
+        assert_eq!(FieldMapping::default().key_for(&record).len(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_folder_source_follows_changes() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("tickets.csv"), "id,subject\n1,A\n2,B\n").unwrap();
+        std::fs::write(dir.path().join("note.md"), "# Plan\nShip it").unwrap();
+        std::fs::write(dir.path().join("image.png"), [0u8; 4]).unwrap();
+        let source = FolderSource {
+            root: dir.path().to_path_buf(),
+            extensions: Vec::new(),
+        };
+
+        let batch = source.poll(None).await.unwrap();
+        assert_eq!(batch.records.len(), 3);
+        let keys: Vec<String> = batch
+            .records
+            .iter()
+            .map(|r| value_text(&r[RECORD_KEY_FIELD]))
+            .collect();
+        assert!(keys.contains(&"tickets.csv#2".to_string()));
+        assert!(keys.contains(&"note.md".to_string()));
+
+        let again = source.poll(batch.cursor.as_deref()).await.unwrap();
+        assert!(again.records.is_empty());
+        assert!(again.cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_source_pages_after_cursor() {
+        let dir = TempDir::new().unwrap();
+        let conn = rusqlite::Connection::open(dir.path().join("db.sqlite")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE tickets (id INTEGER, subject TEXT, updated_at TEXT);
+             INSERT INTO tickets VALUES (1, 'a', '2026-01-01'), (2, 'b', '2026-01-02'),
+                                        (3, 'c', '2026-01-03');",
+        )
+        .unwrap();
+        drop(conn);
+        let source = SqlSource {
+            connection: SqlConnection {
+                driver: SqlDriver::Sqlite,
+                url: "db.sqlite".into(),
+                password_env: None,
+            },
+            query: "SELECT id, subject, updated_at FROM tickets;".into(),
+            cursor_column: "updated_at".into(),
+            batch_size: 2,
+            workspace: dir.path().to_path_buf(),
+        };
+
+        let batch = source.poll(None).await.unwrap();
+        assert_eq!(batch.records.len(), 3);
+        assert_eq!(batch.cursor.as_deref(), Some("2026-01-03"));
+
+        let batch = source.poll(Some("2026-01-02")).await.unwrap();
+        assert_eq!(batch.records.len(), 1);
+        assert_eq!(batch.records[0]["subject"], "c");
+    }
+
+    #[test]
+    fn test_source_config_from_toml() {
+        let config: CdcSourceConfig = toml::from_str(
+            r#"
+            name = "tickets"
+            type = "sql"
+            connection = "support_db"
+            query = "SELECT * FROM tickets"
+            cursor_column = "updated_at"
+            target = "inbox"
+            snapshot = "skip"
+
+            [mapping]
+            key = "id"
+            template = "{subject}"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(config.kind, CdcSourceKind::Sql { .. }));
+        assert_eq!(config.target, CdcTarget::Inbox);
+        assert_eq!(config.snapshot, SnapshotMode::Skip);
+        assert_eq!(config.interval_secs, 300);
+        assert!(config.enabled);
+    }
+}
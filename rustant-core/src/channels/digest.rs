@@ -7,12 +7,30 @@
 //! - Markdown file export to `.rustant/digests/`
 //!
 //! Digest frequency is controlled per-channel via `DigestFrequency`.
-
-use super::intelligence::{ClassifiedMessage, MessageType};
-use crate::config::{DigestFrequency, MessagePriority};
-use chrono::{DateTime, Utc};
+//!
+//! [`DigestScheduler`] adds scheduled channel delivery: each configured source
+//! channel gets a delivery time in its own timezone, a destination channel, and
+//! a [`DigestTemplate`]. Delivered digests are tracked by ID so that a reply or
+//! reaction acknowledges their action items; unacknowledged items carry over
+//! into the next digest. [`DigestRunner`] drives the scheduler against live
+//! channels while the gateway runs.
+
+use super::intelligence::{ClassifiedMessage, MessageClassifier, MessageType};
+use super::manager::{ChannelManager, OutgoingMessage, build_channel_manager};
+use super::scheduler_bridge::{FollowUpReminder, ReminderStatus};
+use super::types::{ChannelMessage, ChannelUser, MessageContent, MessageId};
+use crate::config::{
+    ChannelsConfig, DigestDeliveryConfig, DigestFrequency, DigestSection, DigestTemplate,
+    IntelligenceConfig, MessagePriority,
+};
+use crate::error::RustantError;
+use crate::scheduler::QuietHours;
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc,
+    Weekday,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
@@ -44,6 +62,27 @@ pub struct DigestActionItem {
     pub scheduled: bool,
 }
 
+impl DigestActionItem {
+    /// Stable key used to track acknowledgment across digests.
+    pub fn key(&self) -> String {
+        format!(
+            "{}|{}|{}",
+            self.source_channel, self.source_sender, self.description
+        )
+    }
+}
+
+/// A pending follow-up reminder listed in a digest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestFollowUp {
+    /// What needs follow-up.
+    pub description: String,
+    /// The channel the original message came from.
+    pub source_channel: String,
+    /// When the reminder fires.
+    pub remind_at: DateTime<Utc>,
+}
+
 /// A generated channel digest covering a time period.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelDigest {
@@ -65,9 +104,129 @@ pub struct ChannelDigest {
     pub action_items: Vec<DigestActionItem>,
     /// Per-channel message counts.
     pub channel_counts: HashMap<String, usize>,
+    /// Pending follow-up reminders.
+    #[serde(default)]
+    pub follow_ups: Vec<DigestFollowUp>,
 }
 
 impl ChannelDigest {
+    /// Short reference shown to users for acknowledging this digest.
+    pub fn short_id(&self) -> String {
+        self.id.simple().to_string()[..8].to_string()
+    }
+
+    /// Whether the digest has nothing to show for the given template.
+    pub fn is_empty_for(&self, template: &DigestTemplate) -> bool {
+        !template.sections.iter().any(|section| match section {
+            DigestSection::Highlights => !self.highlights.is_empty(),
+            DigestSection::ActionItems => !self.action_items.is_empty(),
+            DigestSection::FollowUps => !self.follow_ups.is_empty(),
+            DigestSection::Counts => self.total_messages > 0,
+        })
+    }
+
+    /// Render the digest as a plain-text channel message using a template.
+    pub fn render(&self, template: &DigestTemplate) -> String {
+        if template.compact {
+            return self.render_compact(template);
+        }
+
+        let mut out = format!(
+            "Digest {} ({} to {})\n{} messages across {} channels.\n",
+            self.short_id(),
+            self.period_start.format("%Y-%m-%d %H:%M"),
+            self.period_end.format("%Y-%m-%d %H:%M"),
+            self.total_messages,
+            self.channels_covered.len(),
+        );
+        for section in &template.sections {
+            match section {
+                DigestSection::Highlights if !self.highlights.is_empty() => {
+                    out.push_str("\nHighlights:\n");
+                    for h in &self.highlights {
+                        out.push_str(&format!(
+                            "- [{}] {}: {} ({:?})\n",
+                            h.channel, h.sender, h.summary, h.priority
+                        ));
+                    }
+                }
+                DigestSection::ActionItems if !self.action_items.is_empty() => {
+                    out.push_str("\nAction items:\n");
+                    for item in &self.action_items {
+                        out.push_str(&format!(
+                            "- {} ({}, {})\n",
+                            item.description, item.source_channel, item.source_sender
+                        ));
+                    }
+                }
+                DigestSection::FollowUps if !self.follow_ups.is_empty() => {
+                    out.push_str("\nFollow-ups:\n");
+                    for f in &self.follow_ups {
+                        out.push_str(&format!(
+                            "- {} ({}, due {})\n",
+                            f.description,
+                            f.source_channel,
+                            f.remind_at.format("%Y-%m-%d %H:%M UTC")
+                        ));
+                    }
+                }
+                DigestSection::Counts if !self.channel_counts.is_empty() => {
+                    out.push_str("\nCounts:\n");
+                    let mut counts: Vec<_> = self.channel_counts.iter().collect();
+                    counts.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+                    for (channel, count) in counts {
+                        out.push_str(&format!("- {}: {}\n", channel, count));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !self.action_items.is_empty() {
+            out.push_str(&format!(
+                "\nReply to this message (or \"ack {}\") to mark these items as seen.",
+                self.short_id()
+            ));
+        }
+        out
+    }
+
+    /// Render a single line bounded by `template.max_chars`.
+    fn render_compact(&self, template: &DigestTemplate) -> String {
+        let mut parts = Vec::new();
+        for section in &template.sections {
+            match section {
+                DigestSection::Counts => parts.push(format!("{} msgs", self.total_messages)),
+                DigestSection::Highlights if !self.highlights.is_empty() => {
+                    parts.push(format!("{} important", self.highlights.len()))
+                }
+                DigestSection::FollowUps if !self.follow_ups.is_empty() => {
+                    parts.push(format!("{} follow-ups", self.follow_ups.len()))
+                }
+                DigestSection::ActionItems if !self.action_items.is_empty() => {
+                    let first: Vec<&str> = self
+                        .action_items
+                        .iter()
+                        .take(3)
+                        .map(|a| a.description.as_str())
+                        .collect();
+                    parts.push(format!(
+                        "{} to-do: {}",
+                        self.action_items.len(),
+                        first.join("; ")
+                    ));
+                }
+                _ => {}
+            }
+        }
+        let line = format!("Digest {}: {}", self.short_id(), parts.join(", "));
+        if line.chars().count() <= template.max_chars {
+            line
+        } else {
+            let keep = template.max_chars.saturating_sub(3);
+            format!("{}...", line.chars().take(keep).collect::<String>())
+        }
+    }
+
     /// Generate a markdown representation of the digest.
     pub fn to_markdown(&self) -> String {
         let mut md = String::new();
@@ -303,6 +462,7 @@ impl DigestCollector {
             highlights,
             action_items,
             channel_counts,
+            follow_ups: Vec::new(),
         };
 
         // Reset for next period
@@ -351,6 +511,504 @@ impl DigestCollector {
     }
 }
 
+/// Compute the next delivery time strictly after `after`.
+///
+/// Hourly digests fire at the configured minute past every hour, daily digests
+/// at the configured local time, and weekly digests at that time on the
+/// configured weekday (Monday by default). Returns `None` when digests are off.
+pub fn next_delivery_after(
+    frequency: &DigestFrequency,
+    delivery: &DigestDeliveryConfig,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let tz = delivery.tz();
+    let time = NaiveTime::parse_from_str(&delivery.delivery_time, "%H:%M")
+        .unwrap_or_else(|_| NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default());
+    let local = after.with_timezone(&tz);
+    let today = local.date_naive();
+
+    let at = |date: NaiveDate, t: NaiveTime| resolve_local(&tz, date.and_time(t));
+
+    match frequency {
+        DigestFrequency::Off => None,
+        DigestFrequency::Hourly => {
+            let t = NaiveTime::from_hms_opt(local.hour(), time.minute(), 0)?;
+            let candidate = at(today, t);
+            Some(if candidate > after {
+                candidate
+            } else {
+                candidate + Duration::hours(1)
+            })
+        }
+        DigestFrequency::Daily => {
+            let candidate = at(today, time);
+            Some(if candidate > after {
+                candidate
+            } else {
+                at(today.succ_opt()?, time)
+            })
+        }
+        DigestFrequency::Weekly => {
+            let weekday = delivery
+                .weekday
+                .as_deref()
+                .and_then(|d| d.parse::<Weekday>().ok())
+                .unwrap_or(Weekday::Mon);
+            let days_ahead =
+                (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+            let date = today + Duration::days(days_ahead as i64);
+            let candidate = at(date, time);
+            Some(if candidate > after {
+                candidate
+            } else {
+                at(date + Duration::days(7), time)
+            })
+        }
+    }
+}
+
+/// Map a local wall-clock time to UTC, moving forward past DST gaps.
+fn resolve_local(tz: &chrono_tz::Tz, naive: NaiveDateTime) -> DateTime<Utc> {
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(naive + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| naive.and_utc())
+}
+
+/// Outcome of a scheduled delivery attempt for one source channel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DigestDelivery {
+    /// The digest was queued on the channel manager's outgoing queue.
+    Queued {
+        source: String,
+        destination: String,
+        digest_id: Uuid,
+    },
+    /// Nothing to report; no message was sent.
+    Skipped { source: String },
+    /// Delivery is due but postponed until quiet hours end.
+    Deferred { source: String },
+    /// The destination channel is not registered.
+    Failed { source: String, reason: String },
+}
+
+/// Delivery state for one source channel.
+struct ScheduledChannel {
+    frequency: DigestFrequency,
+    delivery: DigestDeliveryConfig,
+    collector: DigestCollector,
+    next_due: Option<DateTime<Utc>>,
+    /// Action items delivered but not yet acknowledged.
+    outstanding: Vec<DigestActionItem>,
+}
+
+/// A delivered digest awaiting acknowledgment.
+struct PendingAck {
+    digest_id: Uuid,
+    short_id: String,
+    source: String,
+    item_keys: Vec<String>,
+}
+
+/// Maximum delivered digests tracked for acknowledgment.
+const MAX_PENDING_ACKS: usize = 256;
+
+/// Schedules per-channel digest delivery and tracks acknowledgments.
+pub struct DigestScheduler {
+    channels: HashMap<String, ScheduledChannel>,
+    pending_acks: VecDeque<PendingAck>,
+    /// Sent message IDs mapped to the digest they carried.
+    sent_messages: HashMap<MessageId, Uuid>,
+}
+
+impl DigestScheduler {
+    /// Create an empty scheduler.
+    pub fn new() -> Self {
+        Self {
+            channels: HashMap::new(),
+            pending_acks: VecDeque::new(),
+            sent_messages: HashMap::new(),
+        }
+    }
+
+    /// Build a scheduler for the named channels that have digests enabled and
+    /// a `digest_delivery` section configured.
+    pub fn from_config(
+        config: &IntelligenceConfig,
+        channel_names: &[&str],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut scheduler = Self::new();
+        for name in channel_names {
+            let channel_config = config.for_channel(name);
+            if let Some(delivery) = &channel_config.digest_delivery {
+                scheduler.add_channel(
+                    name,
+                    channel_config.digest.clone(),
+                    delivery.clone(),
+                    config.digest_dir.clone(),
+                    now,
+                );
+            }
+        }
+        scheduler
+    }
+
+    /// Schedule digest delivery for a source channel.
+    pub fn add_channel(
+        &mut self,
+        source: &str,
+        frequency: DigestFrequency,
+        delivery: DigestDeliveryConfig,
+        digest_dir: PathBuf,
+        now: DateTime<Utc>,
+    ) {
+        if frequency == DigestFrequency::Off {
+            return;
+        }
+        let next_due = next_delivery_after(&frequency, &delivery, now);
+        let mut collector = DigestCollector::new(frequency.clone(), digest_dir);
+        collector.period_start = now;
+        self.channels.insert(
+            source.to_string(),
+            ScheduledChannel {
+                frequency,
+                delivery,
+                collector,
+                next_due,
+                outstanding: Vec::new(),
+            },
+        );
+    }
+
+    /// Names of channels with scheduled delivery.
+    pub fn scheduled_channels(&self) -> Vec<&str> {
+        self.channels.keys().map(|k| k.as_str()).collect()
+    }
+
+    /// When the next digest for a source channel is due.
+    pub fn next_due(&self, source: &str) -> Option<DateTime<Utc>> {
+        self.channels.get(source).and_then(|c| c.next_due)
+    }
+
+    /// Record a classified message from a source channel.
+    pub fn add_message(&mut self, classified: &ClassifiedMessage, channel_name: &str) {
+        if let Some(channel) = self.channels.get_mut(channel_name) {
+            channel.collector.add_message(classified, channel_name);
+        }
+    }
+
+    /// Deliver every digest that is due at `now`.
+    ///
+    /// Digests are pushed onto the manager's outgoing queue; call
+    /// [`ChannelManager::flush_outgoing`] and then [`record_sent`](Self::record_sent)
+    /// so replies to the delivered message can be matched. During quiet hours
+    /// due digests are deferred and keep collecting messages.
+    pub fn tick(
+        &mut self,
+        now: DateTime<Utc>,
+        quiet_hours: Option<&QuietHours>,
+        follow_ups: &[FollowUpReminder],
+        manager: &mut ChannelManager,
+    ) -> Vec<DigestDelivery> {
+        let mut outcomes = Vec::new();
+        let mut sources: Vec<String> = self.channels.keys().cloned().collect();
+        sources.sort();
+
+        for source in sources {
+            let Some(channel) = self.channels.get_mut(&source) else {
+                continue;
+            };
+            if channel.next_due.is_none_or(|due| due > now) {
+                continue;
+            }
+            if quiet_hours.is_some_and(|q| q.is_active(&now)) {
+                outcomes.push(DigestDelivery::Deferred { source });
+                continue;
+            }
+            channel.next_due = next_delivery_after(&channel.frequency, &channel.delivery, now);
+
+            let mut digest = channel
+                .collector
+                .generate()
+                .unwrap_or_else(|| ChannelDigest {
+                    id: Uuid::new_v4(),
+                    period_start: channel.collector.period_start,
+                    period_end: now,
+                    channels_covered: Vec::new(),
+                    total_messages: 0,
+                    summary: String::new(),
+                    highlights: Vec::new(),
+                    action_items: Vec::new(),
+                    channel_counts: HashMap::new(),
+                    follow_ups: Vec::new(),
+                });
+            channel.collector.period_start = now;
+
+            // Unacknowledged items from earlier digests come first.
+            let mut keys: HashSet<String> = HashSet::new();
+            let mut items = Vec::new();
+            for item in channel
+                .outstanding
+                .drain(..)
+                .chain(digest.action_items.drain(..))
+            {
+                if keys.insert(item.key()) {
+                    items.push(item);
+                }
+            }
+            digest.action_items = items;
+            digest.follow_ups = follow_ups
+                .iter()
+                .filter(|r| r.status == ReminderStatus::Pending && r.source_channel == source)
+                .map(|r| DigestFollowUp {
+                    description: r.description.clone(),
+                    source_channel: r.source_channel.clone(),
+                    remind_at: r.remind_at,
+                })
+                .collect();
+
+            let template = &channel.delivery.template;
+            if digest.is_empty_for(template) {
+                tracing::debug!(channel = %source, "Skipping empty digest");
+                outcomes.push(DigestDelivery::Skipped { source });
+                continue;
+            }
+
+            let destination = channel
+                .delivery
+                .destination
+                .clone()
+                .unwrap_or_else(|| source.clone());
+            let Some(channel_type) = manager.channel_type(&destination) else {
+                channel.outstanding = digest.action_items;
+                outcomes.push(DigestDelivery::Failed {
+                    reason: format!("destination channel '{}' is not registered", destination),
+                    source,
+                });
+                continue;
+            };
+
+            let text = digest.render(template);
+            let sender = ChannelUser::new("rustant", channel_type).with_name("Rustant");
+            let msg = ChannelMessage::text(
+                channel_type,
+                channel.delivery.destination_id.clone(),
+                sender,
+                text,
+            )
+            .with_metadata("digest_id", digest.id.to_string())
+            .with_metadata("digest_source", source.clone());
+            manager.enqueue(destination.clone(), msg);
+
+            let item_keys = digest.action_items.iter().map(|i| i.key()).collect();
+            let short_id = digest.short_id();
+            channel.outstanding = digest.action_items;
+            self.pending_acks.push_back(PendingAck {
+                digest_id: digest.id,
+                short_id,
+                source: source.clone(),
+                item_keys,
+            });
+            while self.pending_acks.len() > MAX_PENDING_ACKS {
+                if let Some(old) = self.pending_acks.pop_front() {
+                    self.sent_messages.retain(|_, id| *id != old.digest_id);
+                }
+            }
+            outcomes.push(DigestDelivery::Queued {
+                source,
+                destination,
+                digest_id: digest.id,
+            });
+        }
+        outcomes
+    }
+
+    /// Remember the message IDs of delivered digests so replies can be matched.
+    pub fn record_sent(&mut self, results: &[(OutgoingMessage, Result<MessageId, RustantError>)]) {
+        for (out, result) in results {
+            let (Some(raw), Ok(message_id)) = (out.message.metadata.get("digest_id"), result)
+            else {
+                continue;
+            };
+            if let Ok(digest_id) = Uuid::parse_str(raw) {
+                self.sent_messages.insert(message_id.clone(), digest_id);
+            }
+        }
+    }
+
+    /// Check an incoming message for a digest acknowledgment.
+    ///
+    /// A reply or reaction to a delivered digest message, a message carrying
+    /// `digest_id` metadata, or text of the form `ack <short id>` all count.
+    /// Returns the acknowledged digest ID.
+    pub fn handle_incoming(&mut self, msg: &ChannelMessage) -> Option<Uuid> {
+        let digest_id = msg
+            .reply_to
+            .as_ref()
+            .and_then(|id| self.sent_messages.get(id).copied())
+            .or_else(|| {
+                msg.metadata
+                    .get("digest_id")
+                    .and_then(|raw| Uuid::parse_str(raw).ok())
+            })
+            .or_else(|| {
+                let MessageContent::Text { text } = &msg.content else {
+                    return None;
+                };
+                let reference = text.trim().strip_prefix("ack ")?.trim().to_lowercase();
+                self.pending_acks
+                    .iter()
+                    .find(|p| p.short_id == reference)
+                    .map(|p| p.digest_id)
+            })?;
+        self.acknowledge(digest_id).then_some(digest_id)
+    }
+
+    /// Mark a digest's action items as seen so later digests don't repeat them.
+    pub fn acknowledge(&mut self, digest_id: Uuid) -> bool {
+        let Some(pos) = self
+            .pending_acks
+            .iter()
+            .position(|p| p.digest_id == digest_id)
+        else {
+            return false;
+        };
+        let Some(ack) = self.pending_acks.remove(pos) else {
+            return false;
+        };
+        self.sent_messages.retain(|_, id| *id != digest_id);
+        if let Some(channel) = self.channels.get_mut(&ack.source) {
+            let seen: HashSet<&String> = ack.item_keys.iter().collect();
+            channel
+                .outstanding
+                .retain(|item| !seen.contains(&item.key()));
+        }
+        true
+    }
+
+    /// Number of delivered digests awaiting acknowledgment.
+    pub fn pending_ack_count(&self) -> usize {
+        self.pending_acks.len()
+    }
+}
+
+impl Default for DigestScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How often a spawned [`DigestRunner`] polls channels and checks for due digests.
+const DIGEST_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Drives a [`DigestScheduler`] against live channels.
+///
+/// Each [`step`](Self::step) polls the channels, records acknowledgments and
+/// classified messages, delivers the digests due at the given time and
+/// remembers the sent message IDs.
+pub struct DigestRunner {
+    scheduler: DigestScheduler,
+    manager: ChannelManager,
+    config: IntelligenceConfig,
+}
+
+impl DigestRunner {
+    pub fn new(
+        scheduler: DigestScheduler,
+        manager: ChannelManager,
+        config: IntelligenceConfig,
+    ) -> Self {
+        Self {
+            scheduler,
+            manager,
+            config,
+        }
+    }
+
+    /// Build a runner for the configured channels, or `None` when intelligence
+    /// is off or no channel has `digest_delivery` set.
+    pub fn from_config(
+        config: &IntelligenceConfig,
+        channels: &ChannelsConfig,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let manager = build_channel_manager(channels);
+        let scheduler = DigestScheduler::from_config(config, &manager.channel_names(), now);
+        if scheduler.scheduled_channels().is_empty() {
+            return None;
+        }
+        Some(Self::new(scheduler, manager, config.clone()))
+    }
+
+    pub fn scheduler(&self) -> &DigestScheduler {
+        &self.scheduler
+    }
+
+    /// Poll the channels and deliver the digests due at `now`.
+    pub async fn step(&mut self, now: DateTime<Utc>) -> Vec<DigestDelivery> {
+        for (name, result) in self.manager.poll_all().await {
+            let messages = match result {
+                Ok(messages) => messages,
+                Err(e) => {
+                    tracing::debug!(channel = %name, error = %e, "Digest poll failed");
+                    continue;
+                }
+            };
+            let classifier = MessageClassifier::new(self.config.for_channel(&name).clone());
+            for msg in &messages {
+                if self.scheduler.handle_incoming(msg).is_none() {
+                    self.scheduler.add_message(&classifier.classify(msg), &name);
+                }
+            }
+        }
+
+        let outcomes = self.scheduler.tick(
+            now,
+            self.config.quiet_hours.as_ref(),
+            &[],
+            &mut self.manager,
+        );
+        let results = self.manager.flush_outgoing().await;
+        for (out, result) in &results {
+            if let Err(e) = result {
+                tracing::warn!(channel = %out.channel_name, "Digest delivery failed: {}", e);
+            }
+        }
+        self.scheduler.record_sent(&results);
+        for outcome in &outcomes {
+            if let DigestDelivery::Failed { source, reason } = outcome {
+                tracing::warn!(channel = %source, "Digest not delivered: {}", reason);
+            }
+        }
+        outcomes
+    }
+
+    /// Connect the channels and step once a minute until the task is dropped.
+    pub fn spawn(mut self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            for (name, result) in self.manager.connect_all().await {
+                if let Err(e) = result {
+                    tracing::warn!(channel = %name, "Digest channel failed to connect: {}", e);
+                }
+            }
+            let mut ticker = tokio::time::interval(DIGEST_POLL_INTERVAL);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                ticker.tick().await;
+                self.step(Utc::now()).await;
+            }
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -562,6 +1220,7 @@ mod tests {
                 m.insert("email".to_string(), 5);
                 m
             },
+            follow_ups: vec![],
         };
 
         let md = digest.to_markdown();
@@ -587,6 +1246,7 @@ mod tests {
             highlights: vec![],
             action_items: vec![],
             channel_counts: HashMap::new(),
+            follow_ups: vec![],
         };
         let path = collector.digest_file_path(&digest);
         assert!(path.to_str().unwrap().contains("digest_"));
@@ -673,4 +1333,394 @@ mod tests {
         // The highlight summary should end with "..."
         assert!(digest.highlights[0].summary.ends_with("..."));
     }
+
+    /// Minimal connected channel used as a digest destination.
+    struct SinkChannel {
+        name: String,
+        channel_type: ChannelType,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::channels::Channel for SinkChannel {
+        fn name(&self) -> &str {
+            &self.name
+        }
+        fn channel_type(&self) -> ChannelType {
+            self.channel_type
+        }
+        async fn connect(&mut self) -> Result<(), RustantError> {
+            Ok(())
+        }
+        async fn disconnect(&mut self) -> Result<(), RustantError> {
+            Ok(())
+        }
+        async fn send_message(&self, msg: ChannelMessage) -> Result<MessageId, RustantError> {
+            Ok(msg.id)
+        }
+        async fn receive_messages(&self) -> Result<Vec<ChannelMessage>, RustantError> {
+            Ok(vec![])
+        }
+        fn status(&self) -> crate::channels::ChannelStatus {
+            crate::channels::ChannelStatus::Connected
+        }
+    }
+
+    /// Channel that hands out queued incoming messages and records what it sends.
+    struct ScriptedChannel {
+        name: String,
+        channel_type: ChannelType,
+        inbox: std::sync::Arc<std::sync::Mutex<Vec<ChannelMessage>>>,
+        sent: std::sync::Arc<std::sync::Mutex<Vec<ChannelMessage>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::channels::Channel for ScriptedChannel {
+        fn name(&self) -> &str {
+            &self.name
+        }
+        fn channel_type(&self) -> ChannelType {
+            self.channel_type
+        }
+        async fn connect(&mut self) -> Result<(), RustantError> {
+            Ok(())
+        }
+        async fn disconnect(&mut self) -> Result<(), RustantError> {
+            Ok(())
+        }
+        async fn send_message(&self, msg: ChannelMessage) -> Result<MessageId, RustantError> {
+            let id = msg.id.clone();
+            self.sent.lock().unwrap().push(msg);
+            Ok(id)
+        }
+        async fn receive_messages(&self) -> Result<Vec<ChannelMessage>, RustantError> {
+            Ok(std::mem::take(&mut *self.inbox.lock().unwrap()))
+        }
+        fn status(&self) -> crate::channels::ChannelStatus {
+            crate::channels::ChannelStatus::Connected
+        }
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn scheduler_with(
+        delivery: DigestDeliveryConfig,
+        now: DateTime<Utc>,
+    ) -> (DigestScheduler, ChannelManager) {
+        let mut scheduler = DigestScheduler::new();
+        scheduler.add_channel(
+            "email",
+            DigestFrequency::Daily,
+            delivery,
+            PathBuf::from("/tmp/rustant-test-digests"),
+            now,
+        );
+        let mut manager = ChannelManager::new();
+        manager.register(Box::new(SinkChannel {
+            name: "slack".into(),
+            channel_type: ChannelType::Slack,
+        }));
+        (scheduler, manager)
+    }
+
+    fn slack_delivery() -> DigestDeliveryConfig {
+        DigestDeliveryConfig {
+            delivery_time: "09:00".into(),
+            destination: Some("slack".into()),
+            destination_id: "C-DIGEST".into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_runner_delivers_due_digest_and_records_ack() {
+        let start = utc("2026-03-04T08:00:00Z");
+        let (scheduler, _) = scheduler_with(slack_delivery(), start);
+        let email_inbox = std::sync::Arc::new(std::sync::Mutex::new(vec![ChannelMessage::text(
+            ChannelType::Email,
+            "inbox",
+            ChannelUser::new("alice@example.com", ChannelType::Email),
+            "The quarterly numbers are in the shared folder.",
+        )]));
+        let slack_inbox = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sent = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut manager = ChannelManager::new();
+        manager.register(Box::new(ScriptedChannel {
+            name: "email".into(),
+            channel_type: ChannelType::Email,
+            inbox: email_inbox,
+            sent: sent.clone(),
+        }));
+        manager.register(Box::new(ScriptedChannel {
+            name: "slack".into(),
+            channel_type: ChannelType::Slack,
+            inbox: slack_inbox.clone(),
+            sent: sent.clone(),
+        }));
+        let mut runner = DigestRunner::new(scheduler, manager, IntelligenceConfig::default());
+
+        // Before 09:00 the message is collected but nothing is sent.
+        assert!(runner.step(start).await.is_empty());
+        assert!(sent.lock().unwrap().is_empty());
+
+        let outcomes = runner.step(utc("2026-03-04T09:00:00Z")).await;
+        let [DigestDelivery::Queued { destination, .. }] = &outcomes[..] else {
+            panic!("expected one queued digest, got {:?}", outcomes);
+        };
+        assert_eq!(destination, "slack");
+        let delivered = sent.lock().unwrap().clone();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].channel_id, "C-DIGEST");
+        let MessageContent::Text { text } = &delivered[0].content else {
+            panic!("digest should be text");
+        };
+        assert!(text.contains("1 messages across 1 channels."));
+        assert_eq!(runner.scheduler().pending_ack_count(), 1);
+        assert_eq!(
+            runner.scheduler().next_due("email"),
+            Some(utc("2026-03-05T09:00:00Z"))
+        );
+
+        // A reply to the delivered message acknowledges it on the next step.
+        let mut reply = ChannelMessage::text(
+            ChannelType::Slack,
+            "C-DIGEST",
+            ChannelUser::new("U1", ChannelType::Slack),
+            "thanks",
+        );
+        reply.reply_to = Some(delivered[0].id.clone());
+        slack_inbox.lock().unwrap().push(reply);
+        assert!(runner.step(utc("2026-03-04T09:01:00Z")).await.is_empty());
+        assert_eq!(runner.scheduler().pending_ack_count(), 0);
+    }
+
+    #[test]
+    fn test_next_delivery_respects_timezone_and_frequency() {
+        let delivery = DigestDeliveryConfig {
+            delivery_time: "09:00".into(),
+            timezone: Some("Asia/Kolkata".into()),
+            ..Default::default()
+        };
+        // 02:00 UTC is 07:30 IST — today's 09:00 IST (03:30 UTC) is still ahead.
+        let after = utc("2026-03-04T02:00:00Z");
+        assert_eq!(
+            next_delivery_after(&DigestFrequency::Daily, &delivery, after),
+            Some(utc("2026-03-04T03:30:00Z"))
+        );
+        // Past today's slot rolls to tomorrow.
+        assert_eq!(
+            next_delivery_after(
+                &DigestFrequency::Daily,
+                &delivery,
+                utc("2026-03-04T04:00:00Z")
+            ),
+            Some(utc("2026-03-05T03:30:00Z"))
+        );
+        // 2026-03-04 is a Wednesday; weekly defaults to Monday.
+        assert_eq!(
+            next_delivery_after(&DigestFrequency::Weekly, &delivery, after),
+            Some(utc("2026-03-09T03:30:00Z"))
+        );
+        let utc_delivery = DigestDeliveryConfig {
+            delivery_time: "00:15".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            next_delivery_after(&DigestFrequency::Hourly, &utc_delivery, after),
+            Some(utc("2026-03-04T02:15:00Z"))
+        );
+        assert!(next_delivery_after(&DigestFrequency::Off, &delivery, after).is_none());
+    }
+
+    #[test]
+    fn test_render_template_sections_and_compact() {
+        let mut collector = test_collector();
+        collector.add_message(
+            &make_classified(
+                "Please sign the contract before Friday, legal is waiting on it",
+                MessagePriority::High,
+                MessageType::ActionRequired,
+                ChannelType::Email,
+                "Bob",
+            ),
+            "email",
+        );
+        let digest = collector.generate().unwrap();
+
+        let only_actions = DigestTemplate {
+            sections: vec![DigestSection::ActionItems],
+            ..Default::default()
+        };
+        let text = digest.render(&only_actions);
+        assert!(text.contains("Action items:"));
+        assert!(!text.contains("Highlights:"));
+        assert!(text.contains(&format!("ack {}", digest.short_id())));
+
+        let compact = DigestTemplate {
+            compact: true,
+            max_chars: 60,
+            ..Default::default()
+        };
+        let line = digest.render(&compact);
+        assert!(line.starts_with(&format!("Digest {}:", digest.short_id())));
+        assert!(line.chars().count() <= 60);
+        assert!(!line.contains('\n'));
+    }
+
+    #[test]
+    fn test_scheduler_skips_empty_and_defers_in_quiet_hours() {
+        let now = utc("2026-03-04T08:00:00Z");
+        let (mut scheduler, mut manager) = scheduler_with(slack_delivery(), now);
+        assert_eq!(
+            scheduler.next_due("email"),
+            Some(utc("2026-03-04T09:00:00Z"))
+        );
+
+        // Not due yet.
+        assert!(scheduler.tick(now, None, &[], &mut manager).is_empty());
+
+        // Due, but nothing happened.
+        let due = utc("2026-03-04T09:00:00Z");
+        assert_eq!(
+            scheduler.tick(due, None, &[], &mut manager),
+            vec![DigestDelivery::Skipped {
+                source: "email".into()
+            }]
+        );
+        assert_eq!(manager.outgoing_len(), 0);
+        assert_eq!(
+            scheduler.next_due("email"),
+            Some(utc("2026-03-05T09:00:00Z"))
+        );
+
+        // Due during quiet hours: deferred, schedule unchanged.
+        let quiet = QuietHours {
+            start: "08:00".into(),
+            end: "10:00".into(),
+        };
+        let next = utc("2026-03-05T09:00:00Z");
+        assert_eq!(
+            scheduler.tick(next, Some(&quiet), &[], &mut manager),
+            vec![DigestDelivery::Deferred {
+                source: "email".into()
+            }]
+        );
+        assert_eq!(scheduler.next_due("email"), Some(next));
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_delivers_to_destination_and_tracks_acks() {
+        let now = utc("2026-03-04T08:00:00Z");
+        let (mut scheduler, mut manager) = scheduler_with(slack_delivery(), now);
+        scheduler.add_message(
+            &make_classified(
+                "Review PR #456",
+                MessagePriority::Normal,
+                MessageType::ActionRequired,
+                ChannelType::Email,
+                "Carol",
+            ),
+            "email",
+        );
+        let reminder = FollowUpReminder::new(
+            "Invoice",
+            "email",
+            "Dave",
+            utc("2026-03-04T12:00:00Z"),
+            "Chase invoice",
+            MessagePriority::Normal,
+        );
+
+        let outcomes = scheduler.tick(
+            utc("2026-03-04T09:00:00Z"),
+            None,
+            std::slice::from_ref(&reminder),
+            &mut manager,
+        );
+        let DigestDelivery::Queued {
+            destination,
+            digest_id,
+            ..
+        } = &outcomes[0]
+        else {
+            panic!("expected queued digest, got {:?}", outcomes);
+        };
+        assert_eq!(destination, "slack");
+        assert_eq!(manager.outgoing_len(), 1);
+
+        let sent = manager.flush_outgoing().await;
+        let body = match &sent[0].0.message.content {
+            MessageContent::Text { text } => text.clone(),
+            other => panic!("unexpected content {:?}", other),
+        };
+        assert!(body.contains("Review PR #456"));
+        assert!(body.contains("Chase invoice"));
+        assert_eq!(sent[0].0.message.channel_id, "C-DIGEST");
+        scheduler.record_sent(&sent);
+
+        // Unacknowledged items carry over to the next digest.
+        let outcomes = scheduler.tick(utc("2026-03-05T09:00:00Z"), None, &[], &mut manager);
+        assert!(matches!(outcomes[0], DigestDelivery::Queued { .. }));
+        manager.flush_outgoing().await;
+        assert_eq!(scheduler.pending_ack_count(), 2);
+
+        // A reply to the first digest acknowledges its items.
+        let sent_id = sent[0].1.as_ref().unwrap().clone();
+        let reply = ChannelMessage::text(
+            ChannelType::Slack,
+            "C-DIGEST",
+            ChannelUser::new("u1", ChannelType::Slack),
+            "thanks",
+        )
+        .with_reply_to(sent_id);
+        assert_eq!(scheduler.handle_incoming(&reply), Some(*digest_id));
+
+        // Nothing new and everything seen: the next digest is skipped.
+        let outcomes = scheduler.tick(utc("2026-03-06T09:00:00Z"), None, &[], &mut manager);
+        assert_eq!(
+            outcomes,
+            vec![DigestDelivery::Skipped {
+                source: "email".into()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_scheduler_ack_by_text_reference_and_missing_destination() {
+        let now = utc("2026-03-04T08:00:00Z");
+        let delivery = DigestDeliveryConfig {
+            destination: Some("sms".into()),
+            ..slack_delivery()
+        };
+        let (mut scheduler, mut manager) = scheduler_with(delivery, now);
+        let msg = make_classified(
+            "Call back",
+            MessagePriority::Normal,
+            MessageType::ActionRequired,
+            ChannelType::Email,
+            "Eve",
+        );
+        scheduler.add_message(&msg, "email");
+        let outcomes = scheduler.tick(utc("2026-03-04T09:00:00Z"), None, &[], &mut manager);
+        assert!(matches!(outcomes[0], DigestDelivery::Failed { .. }));
+
+        manager.register(Box::new(SinkChannel {
+            name: "sms".into(),
+            channel_type: ChannelType::Sms,
+        }));
+        let outcomes = scheduler.tick(utc("2026-03-05T09:00:00Z"), None, &[], &mut manager);
+        let DigestDelivery::Queued { digest_id, .. } = outcomes[0] else {
+            panic!("expected queued digest");
+        };
+        let short = digest_id.simple().to_string()[..8].to_string();
+        let ack = ChannelMessage::text(
+            ChannelType::Sms,
+            "+1555",
+            ChannelUser::new("+1555", ChannelType::Sms),
+            format!("ack {}", short),
+        );
+        assert_eq!(scheduler.handle_incoming(&ack), Some(digest_id));
+        assert!(!scheduler.acknowledge(digest_id));
+    }
 }
@@ -2,14 +2,27 @@
 //!
 //! Uses trait abstractions for IMAP reading and SMTP sending.
 //! In tests, mock implementations avoid network calls.
+//!
+//! Incoming mail is received with IMAP IDLE when the server advertises it,
+//! falling back to polling otherwise. Message-ID chains are mapped to
+//! [`ThreadId`]s so replies sent through the channel carry `In-Reply-To`,
+//! `References` and a `Re:` subject, and attachments are saved to the
+//! workspace inbox directory.
 
+use super::email_mime::{self, EmailAttachment};
 use super::{
     Channel, ChannelCapabilities, ChannelMessage, ChannelStatus, ChannelType, ChannelUser,
-    MessageId, StreamingMode,
+    MessageContent, MessageId, StreamingMode, ThreadId,
 };
 use crate::error::{ChannelError, RustantError};
+use crate::secret_ref::{SecretRef, SecretResolver};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 /// Authentication method for the email channel.
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -19,16 +32,15 @@ pub enum EmailAuthMethod {
     #[default]
     Password,
     /// OAuth 2.0 XOAUTH2 SASL authentication (for Gmail, Outlook, etc.).
-    /// When using this method, the `password` field in `EmailConfig` holds
-    /// the OAuth access token, and `username` is the email address.
-    /// Use `gmail_oauth_config()` and `build_xoauth2_token()` from the
-    /// oauth module to obtain and format the token.
+    /// When `oauth_provider` is set in `EmailConfig`, the access token is
+    /// loaded from the credential store (and refreshed when expired) at
+    /// connection time. Otherwise the `password` field holds the token.
     #[serde(rename = "xoauth2")]
     XOAuth2,
 }
 
 /// Configuration for an Email channel.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmailConfig {
     pub imap_host: String,
     pub imap_port: u16,
@@ -37,9 +49,10 @@ pub struct EmailConfig {
     /// Email address (used as the IMAP/SMTP username).
     pub username: String,
     /// Password or OAuth access token (when `auth_method` is `XOAuth2`).
-    /// Prefer `password_env` to avoid storing secrets in config files.
+    /// Supports `SecretRef` format: `"keychain:email_password"`,
+    /// `"env:EMAIL_PASSWORD"`, or inline plaintext (deprecated).
     #[serde(default)]
-    pub password: String,
+    pub password: SecretRef,
     /// Environment variable name containing the password or OAuth token.
     /// When set, this takes precedence over the `password` field.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -49,24 +62,130 @@ pub struct EmailConfig {
     /// Authentication method for IMAP/SMTP connections.
     #[serde(default)]
     pub auth_method: EmailAuthMethod,
+    /// OAuth provider (`"gmail"` or `"outlook"`) whose stored token is used
+    /// for XOAUTH2. Set by `rustant setup email` or `rustant auth login`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth_provider: Option<String>,
+    /// Use IMAP IDLE for push delivery when the server supports it.
+    #[serde(default = "default_true")]
+    pub idle: bool,
+    /// Maximum time to wait in a single IDLE command before re-checking.
+    #[serde(default = "default_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+    /// Polling interval used when IDLE is disabled or unsupported.
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Directory where incoming attachments are saved.
+    #[serde(default = "default_attachments_dir")]
+    pub attachments_dir: PathBuf,
+    /// Attachments larger than this are skipped.
+    #[serde(default = "default_max_attachment_bytes")]
+    pub max_attachment_bytes: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_idle_timeout_secs() -> u64 {
+    300
+}
+
+fn default_poll_interval_secs() -> u64 {
+    30
+}
+
+fn default_attachments_dir() -> PathBuf {
+    PathBuf::from(".rustant/inbox")
+}
+
+fn default_max_attachment_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            imap_host: String::new(),
+            imap_port: 0,
+            smtp_host: String::new(),
+            smtp_port: 0,
+            username: String::new(),
+            password: SecretRef::default(),
+            password_env: None,
+            from_address: String::new(),
+            allowed_senders: Vec::new(),
+            auth_method: EmailAuthMethod::default(),
+            oauth_provider: None,
+            idle: true,
+            idle_timeout_secs: default_idle_timeout_secs(),
+            poll_interval_secs: default_poll_interval_secs(),
+            attachments_dir: default_attachments_dir(),
+            max_attachment_bytes: default_max_attachment_bytes(),
+        }
+    }
 }
 
 impl EmailConfig {
-    /// Resolve the effective password, preferring the environment variable.
+    /// Resolve the effective password, preferring the environment variable
+    /// over the `password` secret reference.
     pub fn resolve_password(&self) -> String {
         if let Some(ref env_var) = self.password_env
             && let Ok(val) = std::env::var(env_var)
         {
             return val;
         }
-        self.password.clone()
+        if self.password.is_empty() {
+            return String::new();
+        }
+        let store = crate::credentials::configured_store();
+        SecretResolver::resolve(&self.password, store.as_ref()).unwrap_or_else(|e| {
+            tracing::warn!("Failed to resolve email password: {}", e);
+            String::new()
+        })
+    }
+
+    /// Whether the access token comes from the OAuth credential store.
+    pub fn uses_oauth_store(&self) -> bool {
+        self.auth_method == EmailAuthMethod::XOAuth2 && self.oauth_provider.is_some()
+    }
+}
+
+/// Resolve the secret used to authenticate: a stored (and refreshed) OAuth
+/// token when an OAuth provider is configured, otherwise the static password.
+async fn resolve_secret(
+    auth_method: &EmailAuthMethod,
+    oauth_provider: Option<&str>,
+    password: &str,
+) -> Result<String, String> {
+    match (auth_method, oauth_provider) {
+        (EmailAuthMethod::XOAuth2, Some(provider)) => {
+            let store = crate::credentials::configured_store();
+            crate::oauth::resolve_access_token(store.as_ref(), provider)
+                .await
+                .map_err(|e| format!("OAuth token error: {e}"))
+        }
+        _ => Ok(password.to_string()),
     }
 }
 
+/// An outgoing email with optional threading headers.
+#[derive(Debug, Clone, Default)]
+pub struct OutgoingEmail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    /// Message-ID to stamp on the email, including angle brackets.
+    pub message_id: String,
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+}
+
 /// Trait for SMTP sending.
 #[async_trait]
 pub trait SmtpSender: Send + Sync {
-    async fn send_email(&self, to: &str, subject: &str, body: &str) -> Result<String, String>;
+    /// Send an email, returning the identifier of the sent message.
+    async fn send_email(&self, email: &OutgoingEmail) -> Result<String, String>;
 }
 
 /// Trait for IMAP receiving.
@@ -74,15 +193,133 @@ pub trait SmtpSender: Send + Sync {
 pub trait ImapReader: Send + Sync {
     async fn fetch_unseen(&self) -> Result<Vec<IncomingEmail>, String>;
     async fn connect(&self) -> Result<(), String>;
+
+    /// Whether the server advertised the IDLE capability on connect.
+    fn supports_idle(&self) -> bool {
+        false
+    }
+
+    /// Wait in IMAP IDLE for up to `timeout`. Returns `true` when the server
+    /// reported new data, `false` on timeout.
+    async fn idle(&self, _timeout: Duration) -> Result<bool, String> {
+        Ok(false)
+    }
 }
 
 /// An incoming email message.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct IncomingEmail {
     pub message_id: String,
     pub from: String,
+    pub from_name: Option<String>,
     pub subject: String,
     pub body: String,
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+    pub attachments: Vec<EmailAttachment>,
+}
+
+/// Headers needed to reply to a known message.
+#[derive(Debug, Clone)]
+struct ReplyContext {
+    /// `References` for a reply: the message's own references plus its id.
+    references: Vec<String>,
+    subject: String,
+}
+
+/// Maps Message-ID chains to threads.
+#[derive(Debug, Default)]
+struct EmailThreads {
+    threads: HashMap<String, ThreadId>,
+    messages: HashMap<String, ReplyContext>,
+    latest: HashMap<ThreadId, String>,
+}
+
+impl EmailThreads {
+    /// Assign an incoming message to a thread, recording it for replies.
+    fn assign(
+        &mut self,
+        message_id: &str,
+        in_reply_to: Option<&str>,
+        references: &[String],
+        subject: &str,
+    ) -> ThreadId {
+        let ancestors: Vec<&str> = references
+            .iter()
+            .map(String::as_str)
+            .chain(in_reply_to)
+            .collect();
+        let thread = ancestors
+            .iter()
+            .find_map(|id| self.threads.get(*id).cloned())
+            .unwrap_or_else(|| ThreadId::new(ancestors.first().copied().unwrap_or(message_id)));
+
+        let mut reply_refs: Vec<String> = if references.is_empty() {
+            in_reply_to.map(str::to_string).into_iter().collect()
+        } else {
+            references.to_vec()
+        };
+        reply_refs.push(message_id.to_string());
+        self.record(message_id, thread.clone(), reply_refs, subject);
+        thread
+    }
+
+    fn record(
+        &mut self,
+        message_id: &str,
+        thread: ThreadId,
+        references: Vec<String>,
+        subject: &str,
+    ) {
+        self.threads.insert(message_id.to_string(), thread.clone());
+        self.messages.insert(
+            message_id.to_string(),
+            ReplyContext {
+                references,
+                subject: subject.to_string(),
+            },
+        );
+        self.latest.insert(thread, message_id.to_string());
+    }
+
+    /// Find the message a reply should target: an explicit id, or the most
+    /// recent message in the given thread.
+    fn reply_target(&self, reply_to: Option<&str>, thread: Option<&ThreadId>) -> Option<String> {
+        reply_to
+            .filter(|id| self.messages.contains_key(*id))
+            .map(str::to_string)
+            .or_else(|| thread.and_then(|t| self.latest.get(t).cloned()))
+    }
+}
+
+/// Prefix a subject with `Re:` unless it already has one.
+fn reply_subject(subject: &str) -> String {
+    if subject.len() >= 3 && subject[..3].eq_ignore_ascii_case("re:") {
+        subject.to_string()
+    } else {
+        format!("Re: {subject}")
+    }
+}
+
+/// Reduce a Message-ID or filename to a safe single path component.
+pub(super) fn sanitize_component(value: &str) -> String {
+    let cleaned: String = value
+        .trim_matches(|c| c == '<' || c == '>')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '@') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.');
+    if cleaned.is_empty() {
+        "attachment".to_string()
+    } else {
+        cleaned.to_string()
+    }
 }
 
 /// Email channel.
@@ -92,6 +329,8 @@ pub struct EmailChannel {
     smtp: Box<dyn SmtpSender>,
     imap: Box<dyn ImapReader>,
     name: String,
+    threads: Mutex<EmailThreads>,
+    idle_failed: AtomicBool,
 }
 
 impl EmailChannel {
@@ -102,6 +341,8 @@ impl EmailChannel {
             smtp,
             imap,
             name: "email".to_string(),
+            threads: Mutex::new(EmailThreads::default()),
+            idle_failed: AtomicBool::new(false),
         }
     }
 
@@ -109,6 +350,144 @@ impl EmailChannel {
         self.name = name.into();
         self
     }
+
+    /// Whether incoming mail is currently received via IMAP IDLE.
+    pub fn idle_active(&self) -> bool {
+        self.config.idle && self.imap.supports_idle() && !self.idle_failed.load(Ordering::Relaxed)
+    }
+
+    async fn fetch(&self) -> Result<Vec<IncomingEmail>, RustantError> {
+        self.imap.fetch_unseen().await.map_err(|e| {
+            RustantError::Channel(ChannelError::ConnectionFailed {
+                name: self.name.clone(),
+                message: e,
+            })
+        })
+    }
+
+    fn sender_allowed(&self, from: &str) -> bool {
+        self.config.allowed_senders.is_empty()
+            || self
+                .config
+                .allowed_senders
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(from))
+    }
+
+    fn new_message_id(&self) -> String {
+        let domain = self
+            .config
+            .from_address
+            .rsplit_once('@')
+            .map(|(_, d)| d)
+            .filter(|d| !d.is_empty())
+            .unwrap_or("rustant.local");
+        format!("<{}@{}>", uuid::Uuid::new_v4(), domain)
+    }
+
+    /// Save attachments within the size cap, returning `(path, attachment)`
+    /// pairs for saved files and the names of skipped ones.
+    async fn save_attachments<'a>(
+        &self,
+        message_id: &str,
+        attachments: &'a [EmailAttachment],
+    ) -> (Vec<(PathBuf, &'a EmailAttachment)>, Vec<String>) {
+        let mut saved = Vec::new();
+        let mut skipped = Vec::new();
+        if attachments.is_empty() {
+            return (saved, skipped);
+        }
+        let dir = self
+            .config
+            .attachments_dir
+            .join(sanitize_component(message_id));
+        for attachment in attachments {
+            if attachment.data.len() as u64 > self.config.max_attachment_bytes {
+                tracing::warn!(
+                    channel = %self.name,
+                    filename = %attachment.filename,
+                    size = attachment.data.len(),
+                    "Skipping email attachment over size cap"
+                );
+                skipped.push(attachment.filename.clone());
+                continue;
+            }
+            match write_attachment(&dir, attachment).await {
+                Ok(path) => saved.push((path, attachment)),
+                Err(e) => {
+                    tracing::warn!(
+                        channel = %self.name,
+                        filename = %attachment.filename,
+                        "Failed to save email attachment: {}", e
+                    );
+                    skipped.push(attachment.filename.clone());
+                }
+            }
+        }
+        (saved, skipped)
+    }
+
+    async fn to_channel_messages(&self, email: IncomingEmail) -> Vec<ChannelMessage> {
+        let thread = self
+            .threads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .assign(
+                &email.message_id,
+                email.in_reply_to.as_deref(),
+                &email.references,
+                &email.subject,
+            );
+        let (saved, skipped) = self
+            .save_attachments(&email.message_id, &email.attachments)
+            .await;
+
+        let mut sender = ChannelUser::new(&email.from, ChannelType::Email);
+        if let Some(name) = &email.from_name {
+            sender = sender.with_name(name);
+        }
+        let mut text =
+            ChannelMessage::text(ChannelType::Email, &email.from, sender.clone(), &email.body)
+                .with_thread(thread.clone())
+                .with_metadata("subject", &email.subject)
+                .with_metadata("message_id", &email.message_id);
+        text.id = MessageId::new(&email.message_id);
+        if let Some(parent) = &email.in_reply_to {
+            text = text.with_reply_to(MessageId::new(parent));
+        }
+        if !saved.is_empty() {
+            let paths: Vec<String> = saved.iter().map(|(p, _)| p.display().to_string()).collect();
+            text = text.with_metadata("attachments", paths.join("\n"));
+        }
+        if !skipped.is_empty() {
+            text = text.with_metadata("skipped_attachments", skipped.join("\n"));
+        }
+
+        let mut messages = vec![text];
+        for (path, attachment) in saved {
+            let mut file =
+                ChannelMessage::text(ChannelType::Email, &email.from, sender.clone(), "")
+                    .with_thread(thread.clone())
+                    .with_reply_to(MessageId::new(&email.message_id))
+                    .with_metadata("subject", &email.subject)
+                    .with_metadata("content_type", &attachment.content_type);
+            file.content = MessageContent::File {
+                url: path.display().to_string(),
+                filename: attachment.filename.clone(),
+                size_bytes: Some(attachment.data.len() as u64),
+            };
+            messages.push(file);
+        }
+        messages
+    }
+}
+
+/// Write one attachment into `dir`, returning its path.
+async fn write_attachment(dir: &Path, attachment: &EmailAttachment) -> std::io::Result<PathBuf> {
+    tokio::fs::create_dir_all(dir).await?;
+    let path = dir.join(sanitize_component(&attachment.filename));
+    tokio::fs::write(&path, &attachment.data).await?;
+    Ok(path)
 }
 
 #[async_trait]
@@ -127,8 +506,9 @@ impl Channel for EmailChannel {
                 name: self.name.clone(),
             }));
         }
-        // Both Password and XOAuth2 require a non-empty password/token
-        if self.config.resolve_password().is_empty() {
+        // A password or token is required unless the token comes from the
+        // OAuth credential store.
+        if !self.config.uses_oauth_store() && self.config.resolve_password().is_empty() {
             return Err(RustantError::Channel(ChannelError::AuthFailed {
                 name: self.name.clone(),
             }));
@@ -139,6 +519,7 @@ impl Channel for EmailChannel {
                 message: e,
             })
         })?;
+        self.idle_failed.store(false, Ordering::Relaxed);
         self.status = ChannelStatus::Connected;
         Ok(())
     }
@@ -150,14 +531,41 @@ impl Channel for EmailChannel {
 
     async fn send_message(&self, msg: ChannelMessage) -> Result<MessageId, RustantError> {
         let text = msg.content.as_text().unwrap_or("");
-        let subject = msg
-            .metadata
-            .get("subject")
-            .map(|s| s.as_str())
-            .unwrap_or("Message from Rustant");
+        let explicit_subject = msg.metadata.get("subject").map(|s| s.as_str());
+        let message_id = self.new_message_id();
+
+        let mut email = OutgoingEmail {
+            to: msg.channel_id.clone(),
+            subject: explicit_subject
+                .unwrap_or("Message from Rustant")
+                .to_string(),
+            body: text.to_string(),
+            message_id: message_id.clone(),
+            ..Default::default()
+        };
+
+        {
+            let mut threads = self.threads.lock().unwrap_or_else(|e| e.into_inner());
+            let target = threads.reply_target(
+                msg.reply_to.as_ref().map(|id| id.0.as_str()),
+                msg.thread_id.as_ref(),
+            );
+            if let Some(target) = target
+                && let Some(ctx) = threads.messages.get(&target).cloned()
+            {
+                email.subject = reply_subject(explicit_subject.unwrap_or(&ctx.subject));
+                email.in_reply_to = Some(target.clone());
+                email.references = ctx.references.clone();
+
+                let thread = threads.threads[&target].clone();
+                let mut own_refs = ctx.references;
+                own_refs.push(message_id.clone());
+                threads.record(&message_id, thread, own_refs, &email.subject);
+            }
+        }
 
         self.smtp
-            .send_email(&msg.channel_id, subject, text)
+            .send_email(&email)
             .await
             .map(MessageId::new)
             .map_err(|e| {
@@ -169,26 +577,29 @@ impl Channel for EmailChannel {
     }
 
     async fn receive_messages(&self) -> Result<Vec<ChannelMessage>, RustantError> {
-        let emails = self.imap.fetch_unseen().await.map_err(|e| {
-            RustantError::Channel(ChannelError::ConnectionFailed {
-                name: self.name.clone(),
-                message: e,
-            })
-        })?;
-
-        let messages = emails
-            .into_iter()
-            .filter(|e| {
-                self.config.allowed_senders.is_empty()
-                    || self.config.allowed_senders.contains(&e.from)
-            })
-            .map(|e| {
-                let sender = ChannelUser::new(&e.from, ChannelType::Email);
-                ChannelMessage::text(ChannelType::Email, &e.from, sender, &e.body)
-                    .with_metadata("subject", &e.subject)
-            })
-            .collect();
+        let mut emails = self.fetch().await?;
+
+        if emails.is_empty() && self.idle_active() {
+            let timeout = Duration::from_secs(self.config.idle_timeout_secs);
+            match self.imap.idle(timeout).await {
+                Ok(true) => emails = self.fetch().await?,
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::warn!(
+                        channel = %self.name,
+                        "IMAP IDLE failed, falling back to polling: {}", e
+                    );
+                    self.idle_failed.store(true, Ordering::Relaxed);
+                }
+            }
+        }
 
+        let mut messages = Vec::new();
+        for email in emails {
+            if self.sender_allowed(&email.from) {
+                messages.extend(self.to_channel_messages(email).await);
+            }
+        }
         Ok(messages)
     }
 
@@ -198,7 +609,7 @@ impl Channel for EmailChannel {
 
     fn capabilities(&self) -> ChannelCapabilities {
         ChannelCapabilities {
-            supports_threads: false,
+            supports_threads: true,
             supports_reactions: false,
             supports_files: true,
             supports_voice: false,
@@ -210,7 +621,13 @@ impl Channel for EmailChannel {
     }
 
     fn streaming_mode(&self) -> StreamingMode {
-        StreamingMode::Polling { interval_ms: 30000 }
+        if self.idle_active() {
+            StreamingMode::LongPolling
+        } else {
+            StreamingMode::Polling {
+                interval_ms: self.config.poll_interval_secs * 1000,
+            }
+        }
     }
 }
 
@@ -223,6 +640,8 @@ pub struct RealSmtp {
     from_address: String,
     /// Authentication method — when `XOAuth2`, uses SASL XOAUTH2 mechanism.
     pub auth_method: EmailAuthMethod,
+    /// OAuth provider whose stored token is used for XOAUTH2.
+    pub oauth_provider: Option<String>,
 }
 
 impl RealSmtp {
@@ -241,30 +660,54 @@ impl RealSmtp {
             password,
             from_address,
             auth_method,
+            oauth_provider: None,
         }
     }
+
+    /// Load the XOAUTH2 token for `provider` from the credential store.
+    pub fn with_oauth_provider(mut self, provider: Option<String>) -> Self {
+        self.oauth_provider = provider;
+        self
+    }
 }
 
 #[async_trait]
 impl SmtpSender for RealSmtp {
-    async fn send_email(&self, to: &str, subject: &str, body: &str) -> Result<String, String> {
-        let email = lettre::Message::builder()
+    async fn send_email(&self, email: &OutgoingEmail) -> Result<String, String> {
+        let mut builder = lettre::Message::builder()
             .from(
                 self.from_address
                     .parse()
                     .map_err(|e| format!("Invalid from address: {e}"))?,
             )
-            .to(to.parse().map_err(|e| format!("Invalid to address: {e}"))?)
-            .subject(subject)
-            .body(body.to_string())
+            .to(email
+                .to
+                .parse()
+                .map_err(|e| format!("Invalid to address: {e}"))?)
+            .subject(&email.subject)
+            .message_id(Some(email.message_id.clone()));
+        if let Some(parent) = &email.in_reply_to {
+            builder = builder.in_reply_to(parent.clone());
+        }
+        for reference in &email.references {
+            builder = builder.references(reference.clone());
+        }
+        let message = builder
+            .body(email.body.clone())
             .map_err(|e| format!("Failed to build email: {e}"))?;
 
+        let secret = resolve_secret(
+            &self.auth_method,
+            self.oauth_provider.as_deref(),
+            &self.password,
+        )
+        .await?;
         let creds = lettre::transport::smtp::authentication::Credentials::new(
             self.username.clone(),
-            self.password.clone(),
+            secret,
         );
 
-        let mut builder =
+        let mut transport =
             lettre::AsyncSmtpTransport::<lettre::Tokio1Executor>::starttls_relay(&self.host)
                 .map_err(|e| format!("SMTP relay error: {e}"))?
                 .port(self.port)
@@ -274,18 +717,18 @@ impl SmtpSender for RealSmtp {
         // With Password auth, lettre auto-negotiates the mechanism.
         if self.auth_method == EmailAuthMethod::XOAuth2 {
             use lettre::transport::smtp::authentication::Mechanism;
-            builder = builder.authentication(vec![Mechanism::Xoauth2]);
+            transport = transport.authentication(vec![Mechanism::Xoauth2]);
         }
 
-        let mailer = builder.build();
+        let mailer = transport.build();
 
         use lettre::AsyncTransport;
-        let response = mailer
-            .send(email)
+        mailer
+            .send(message)
             .await
             .map_err(|e| format!("SMTP send error: {e}"))?;
 
-        Ok(format!("{}", response.code()))
+        Ok(email.message_id.clone())
     }
 }
 
@@ -323,6 +766,8 @@ impl async_imap::Authenticator for XOAuth2Authenticator {
     }
 }
 
+type ImapSession = async_imap::Session<tokio_native_tls::TlsStream<tokio::net::TcpStream>>;
+
 /// Real IMAP reader using async-imap.
 pub struct RealImap {
     host: String,
@@ -331,6 +776,9 @@ pub struct RealImap {
     password: String,
     /// Authentication method — when `XOAuth2`, uses SASL XOAUTH2 instead of plain login.
     pub auth_method: EmailAuthMethod,
+    /// OAuth provider whose stored token is used for XOAUTH2.
+    pub oauth_provider: Option<String>,
+    idle_supported: AtomicBool,
 }
 
 impl RealImap {
@@ -347,13 +795,19 @@ impl RealImap {
             username,
             password,
             auth_method,
+            oauth_provider: None,
+            idle_supported: AtomicBool::new(false),
         }
     }
-}
 
-#[async_trait]
-impl ImapReader for RealImap {
-    async fn fetch_unseen(&self) -> Result<Vec<IncomingEmail>, String> {
+    /// Load the XOAUTH2 token for `provider` from the credential store.
+    pub fn with_oauth_provider(mut self, provider: Option<String>) -> Self {
+        self.oauth_provider = provider;
+        self
+    }
+
+    /// Open an authenticated session.
+    async fn session(&self) -> Result<ImapSession, String> {
         let tcp = tokio::net::TcpStream::connect((self.host.as_str(), self.port))
             .await
             .map_err(|e| format!("TCP connect error: {e}"))?;
@@ -378,19 +832,33 @@ impl ImapReader for RealImap {
             .map_err(|e| format!("IMAP greeting read error: {e}"))?
             .ok_or_else(|| "IMAP server closed connection before greeting".to_string())?;
 
-        let mut session = match self.auth_method {
+        let secret = resolve_secret(
+            &self.auth_method,
+            self.oauth_provider.as_deref(),
+            &self.password,
+        )
+        .await?;
+
+        match self.auth_method {
             EmailAuthMethod::XOAuth2 => {
-                let auth = XOAuth2Authenticator::new(&self.username, &self.password);
+                let auth = XOAuth2Authenticator::new(&self.username, &secret);
                 client
                     .authenticate("XOAUTH2", auth)
                     .await
-                    .map_err(|e| format!("IMAP XOAUTH2 auth error: {}", e.0))?
+                    .map_err(|e| format!("IMAP XOAUTH2 auth error: {}", e.0))
             }
             EmailAuthMethod::Password => client
-                .login(&self.username, &self.password)
+                .login(&self.username, &secret)
                 .await
-                .map_err(|e| format!("IMAP login error: {}", e.0))?,
-        };
+                .map_err(|e| format!("IMAP login error: {}", e.0)),
+        }
+    }
+}
+
+#[async_trait]
+impl ImapReader for RealImap {
+    async fn fetch_unseen(&self) -> Result<Vec<IncomingEmail>, String> {
+        let mut session = self.session().await?;
 
         session
             .select("INBOX")
@@ -423,24 +891,18 @@ impl ImapReader for RealImap {
 
             for msg in &messages {
                 if let Some(body_bytes) = msg.body() {
-                    let raw = String::from_utf8_lossy(body_bytes).to_string();
-                    let from = raw
-                        .lines()
-                        .find(|l| l.starts_with("From:"))
-                        .map(|l| l.trim_start_matches("From:").trim().to_string())
-                        .unwrap_or_default();
-                    let subject = raw
-                        .lines()
-                        .find(|l| l.starts_with("Subject:"))
-                        .map(|l| l.trim_start_matches("Subject:").trim().to_string())
-                        .unwrap_or_default();
-                    let body_text = raw.split("\r\n\r\n").nth(1).unwrap_or("").to_string();
-
+                    let parsed = email_mime::parse_message(body_bytes);
                     emails.push(IncomingEmail {
-                        message_id: format!("imap-{}", msg.message),
-                        from,
-                        subject,
-                        body: body_text,
+                        message_id: parsed
+                            .message_id
+                            .unwrap_or_else(|| format!("imap-{}", msg.message)),
+                        from: parsed.from,
+                        from_name: parsed.from_name,
+                        subject: parsed.subject,
+                        body: parsed.text,
+                        in_reply_to: parsed.in_reply_to,
+                        references: parsed.references,
+                        attachments: parsed.attachments,
                     });
                 }
             }
@@ -451,43 +913,46 @@ impl ImapReader for RealImap {
     }
 
     async fn connect(&self) -> Result<(), String> {
-        let tcp = tokio::net::TcpStream::connect((self.host.as_str(), self.port))
+        let mut session = self.session().await?;
+        let idle = session
+            .capabilities()
             .await
-            .map_err(|e| format!("TCP connect error: {e}"))?;
-
-        let native_tls_connector =
-            native_tls::TlsConnector::new().map_err(|e| format!("TLS connector error: {e}"))?;
-        let tls_connector = tokio_native_tls::TlsConnector::from(native_tls_connector);
-        let tls_stream = tls_connector
-            .connect(&self.host, tcp)
-            .await
-            .map_err(|e| format!("TLS connect error: {e}"))?;
+            .map(|caps| caps.has_str("IDLE"))
+            .unwrap_or(false);
+        self.idle_supported.store(idle, Ordering::Relaxed);
+        let _ = session.logout().await;
+        Ok(())
+    }
 
-        let mut client = async_imap::Client::new(tls_stream);
+    fn supports_idle(&self) -> bool {
+        self.idle_supported.load(Ordering::Relaxed)
+    }
 
-        // Read the server greeting — required before authenticate().
-        client
-            .read_response()
+    async fn idle(&self, timeout: Duration) -> Result<bool, String> {
+        let mut session = self.session().await?;
+        session
+            .select("INBOX")
             .await
-            .map_err(|e| format!("IMAP greeting read error: {e}"))?
-            .ok_or_else(|| "IMAP server closed connection before greeting".to_string())?;
+            .map_err(|e| format!("IMAP select error: {e}"))?;
 
-        let mut session = match self.auth_method {
-            EmailAuthMethod::XOAuth2 => {
-                let auth = XOAuth2Authenticator::new(&self.username, &self.password);
-                client
-                    .authenticate("XOAUTH2", auth)
-                    .await
-                    .map_err(|e| format!("IMAP XOAUTH2 auth error: {}", e.0))?
-            }
-            EmailAuthMethod::Password => client
-                .login(&self.username, &self.password)
-                .await
-                .map_err(|e| format!("IMAP login error: {}", e.0))?,
-        };
+        let mut handle = session.idle();
+        handle
+            .init()
+            .await
+            .map_err(|e| format!("IMAP IDLE error: {e}"))?;
+        let (wait, _interrupt) = handle.wait_with_timeout(timeout);
+        let response = wait.await.map_err(|e| format!("IMAP IDLE error: {e}"))?;
+        let new_data = matches!(
+            response,
+            async_imap::extensions::idle::IdleResponse::NewData(_)
+        );
 
+        let mut session = handle
+            .done()
+            .await
+            .map_err(|e| format!("IMAP IDLE done error: {e}"))?;
         let _ = session.logout().await;
-        Ok(())
+        Ok(new_data)
     }
 }
 
@@ -501,14 +966,16 @@ pub fn create_email_channel(config: EmailConfig) -> EmailChannel {
         resolved_password.clone(),
         config.from_address.clone(),
         config.auth_method.clone(),
-    );
+    )
+    .with_oauth_provider(config.oauth_provider.clone());
     let imap = RealImap::new(
         config.imap_host.clone(),
         config.imap_port,
         config.username.clone(),
         resolved_password,
         config.auth_method.clone(),
-    );
+    )
+    .with_oauth_provider(config.oauth_provider.clone());
     EmailChannel::new(config, Box::new(smtp), Box::new(imap))
 }
 
@@ -520,12 +987,7 @@ mod tests {
 
     #[async_trait]
     impl SmtpSender for MockSmtp {
-        async fn send_email(
-            &self,
-            _to: &str,
-            _subject: &str,
-            _body: &str,
-        ) -> Result<String, String> {
+        async fn send_email(&self, _email: &OutgoingEmail) -> Result<String, String> {
             Ok("email-id-1".to_string())
         }
     }
@@ -540,6 +1002,7 @@ mod tests {
                 from: "alice@example.com".into(),
                 subject: "Test".into(),
                 body: "hello email".into(),
+                ..Default::default()
             }])
         }
         async fn connect(&self) -> Result<(), String> {
@@ -603,7 +1066,7 @@ mod tests {
             Box::new(MockImap),
         );
         let caps = ch.capabilities();
-        assert!(!caps.supports_threads);
+        assert!(caps.supports_threads);
         assert!(caps.supports_files);
         assert!(caps.max_message_length.is_none());
     }
@@ -780,4 +1243,280 @@ mod tests {
         // Should not panic — auth_method is passed through to RealImap/RealSmtp
         let _ch = create_email_channel(config);
     }
+
+    // ── Threading, attachments and IDLE ─────────────────────────────────
+
+    use std::sync::Arc;
+
+    #[derive(Clone, Default)]
+    struct RecordingSmtp {
+        sent: Arc<Mutex<Vec<OutgoingEmail>>>,
+    }
+
+    #[async_trait]
+    impl SmtpSender for RecordingSmtp {
+        async fn send_email(&self, email: &OutgoingEmail) -> Result<String, String> {
+            self.sent.lock().unwrap().push(email.clone());
+            Ok(email.message_id.clone())
+        }
+    }
+
+    /// Returns one scripted batch per `fetch_unseen` call.
+    #[derive(Default)]
+    struct ScriptedImap {
+        batches: Mutex<Vec<Vec<IncomingEmail>>>,
+        idle: bool,
+        idle_result: Option<Result<bool, String>>,
+    }
+
+    impl ScriptedImap {
+        fn new(batches: Vec<Vec<IncomingEmail>>) -> Self {
+            Self {
+                batches: Mutex::new(batches),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ImapReader for ScriptedImap {
+        async fn fetch_unseen(&self) -> Result<Vec<IncomingEmail>, String> {
+            let mut batches = self.batches.lock().unwrap();
+            Ok(if batches.is_empty() {
+                Vec::new()
+            } else {
+                batches.remove(0)
+            })
+        }
+        async fn connect(&self) -> Result<(), String> {
+            Ok(())
+        }
+        fn supports_idle(&self) -> bool {
+            self.idle
+        }
+        async fn idle(&self, _timeout: Duration) -> Result<bool, String> {
+            self.idle_result.clone().unwrap_or(Ok(false))
+        }
+    }
+
+    fn incoming(id: &str, in_reply_to: Option<&str>, references: &[&str]) -> IncomingEmail {
+        IncomingEmail {
+            message_id: id.into(),
+            from: "alice@example.com".into(),
+            subject: "Quarterly report".into(),
+            body: "body".into(),
+            in_reply_to: in_reply_to.map(String::from),
+            references: references.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn bot_config() -> EmailConfig {
+        EmailConfig {
+            username: "bot@example.com".into(),
+            password: "pass".into(),
+            from_address: "bot@example.com".into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_email_threads_group_reply_chain() {
+        let imap = ScriptedImap::new(vec![
+            vec![incoming("<a@x>", None, &[])],
+            vec![
+                incoming("<b@x>", Some("<a@x>"), &["<a@x>"]),
+                incoming("<c@x>", Some("<zz@x>"), &["<a@x>", "<zz@x>"]),
+                incoming("<d@x>", None, &[]),
+            ],
+        ]);
+        let ch = EmailChannel::new(bot_config(), Box::new(MockSmtp), Box::new(imap));
+
+        let first = ch.receive_messages().await.unwrap();
+        assert_eq!(first[0].id.0, "<a@x>");
+        assert_eq!(first[0].thread_id, Some(ThreadId::new("<a@x>")));
+        assert!(first[0].reply_to.is_none());
+
+        let rest = ch.receive_messages().await.unwrap();
+        assert_eq!(rest[0].thread_id, Some(ThreadId::new("<a@x>")));
+        assert_eq!(rest[0].reply_to, Some(MessageId::new("<a@x>")));
+        // Unknown direct parent, but a known ancestor in References.
+        assert_eq!(rest[1].thread_id, Some(ThreadId::new("<a@x>")));
+        assert_eq!(rest[2].thread_id, Some(ThreadId::new("<d@x>")));
+    }
+
+    #[tokio::test]
+    async fn test_email_reply_sets_threading_headers() {
+        let smtp = RecordingSmtp::default();
+        let imap = ScriptedImap::new(vec![vec![incoming("<b@x>", Some("<a@x>"), &["<a@x>"])]]);
+        let ch = EmailChannel::new(bot_config(), Box::new(smtp.clone()), Box::new(imap));
+        let received = ch.receive_messages().await.unwrap();
+
+        let sender = ChannelUser::new("bot@example.com", ChannelType::Email);
+        let reply = ChannelMessage::text(ChannelType::Email, "alice@example.com", sender, "ok")
+            .with_thread(received[0].thread_id.clone().unwrap());
+        let id = ch.send_message(reply).await.unwrap();
+
+        let sent = smtp.sent.lock().unwrap()[0].clone();
+        assert_eq!(sent.subject, "Re: Quarterly report");
+        assert_eq!(sent.in_reply_to.as_deref(), Some("<b@x>"));
+        assert_eq!(sent.references, vec!["<a@x>", "<b@x>"]);
+        assert_eq!(id.0, sent.message_id);
+        assert!(sent.message_id.ends_with("@example.com>"));
+
+        // A follow-up in the same thread replies to the agent's own message.
+        let sender = ChannelUser::new("bot@example.com", ChannelType::Email);
+        let follow_up =
+            ChannelMessage::text(ChannelType::Email, "alice@example.com", sender, "more")
+                .with_thread(ThreadId::new("<a@x>"));
+        ch.send_message(follow_up).await.unwrap();
+        let second = smtp.sent.lock().unwrap()[1].clone();
+        assert_eq!(second.subject, "Re: Quarterly report");
+        assert_eq!(second.in_reply_to.as_deref(), Some(id.0.as_str()));
+        assert_eq!(second.references.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_email_new_message_has_no_threading_headers() {
+        let smtp = RecordingSmtp::default();
+        let ch = EmailChannel::new(
+            bot_config(),
+            Box::new(smtp.clone()),
+            Box::new(ScriptedImap::default()),
+        );
+        let sender = ChannelUser::new("bot@example.com", ChannelType::Email);
+        let msg = ChannelMessage::text(ChannelType::Email, "alice@example.com", sender, "hi")
+            .with_metadata("subject", "Hello");
+        ch.send_message(msg).await.unwrap();
+
+        let sent = smtp.sent.lock().unwrap()[0].clone();
+        assert_eq!(sent.subject, "Hello");
+        assert!(sent.in_reply_to.is_none());
+        assert!(sent.references.is_empty());
+    }
+
+    #[test]
+    fn test_reply_subject_prefix() {
+        assert_eq!(reply_subject("Hello"), "Re: Hello");
+        assert_eq!(reply_subject("RE: Hello"), "RE: Hello");
+        assert_eq!(reply_subject(""), "Re: ");
+    }
+
+    #[tokio::test]
+    async fn test_email_attachments_saved_under_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = EmailConfig {
+            attachments_dir: dir.path().to_path_buf(),
+            max_attachment_bytes: 8,
+            ..bot_config()
+        };
+        let mut email = incoming("<m1@x>", None, &[]);
+        email.attachments = vec![
+            EmailAttachment {
+                filename: "../notes.txt".into(),
+                content_type: "text/plain".into(),
+                data: b"small".to_vec(),
+            },
+            EmailAttachment {
+                filename: "big.bin".into(),
+                content_type: "application/octet-stream".into(),
+                data: vec![0; 64],
+            },
+        ];
+        let imap = ScriptedImap::new(vec![vec![email]]);
+        let ch = EmailChannel::new(config, Box::new(MockSmtp), Box::new(imap));
+
+        let msgs = ch.receive_messages().await.unwrap();
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(
+            msgs[0]
+                .metadata
+                .get("skipped_attachments")
+                .map(|s| s.as_str()),
+            Some("big.bin")
+        );
+        let MessageContent::File {
+            url,
+            filename,
+            size_bytes,
+        } = &msgs[1].content
+        else {
+            panic!("expected file content");
+        };
+        assert_eq!(filename, "../notes.txt");
+        assert_eq!(*size_bytes, Some(5));
+        let path = PathBuf::from(url);
+        assert!(path.starts_with(dir.path().join("m1@x")));
+        assert_eq!(std::fs::read(&path).unwrap(), b"small");
+        assert_eq!(msgs[0].metadata.get("attachments"), Some(url));
+        assert_eq!(msgs[1].reply_to, Some(MessageId::new("<m1@x>")));
+        assert_eq!(msgs[1].thread_id, msgs[0].thread_id);
+    }
+
+    #[tokio::test]
+    async fn test_email_idle_refetches_on_new_data() {
+        let imap = ScriptedImap {
+            batches: Mutex::new(vec![Vec::new(), vec![incoming("<n@x>", None, &[])]]),
+            idle: true,
+            idle_result: Some(Ok(true)),
+        };
+        let ch = EmailChannel::new(bot_config(), Box::new(MockSmtp), Box::new(imap));
+        assert_eq!(ch.streaming_mode(), StreamingMode::LongPolling);
+
+        let msgs = ch.receive_messages().await.unwrap();
+        assert_eq!(msgs.len(), 1);
+        assert!(ch.idle_active());
+    }
+
+    #[tokio::test]
+    async fn test_email_idle_failure_falls_back_to_polling() {
+        let imap = ScriptedImap {
+            idle: true,
+            idle_result: Some(Err("IDLE not permitted".into())),
+            ..Default::default()
+        };
+        let ch = EmailChannel::new(bot_config(), Box::new(MockSmtp), Box::new(imap));
+        assert!(ch.idle_active());
+
+        assert!(ch.receive_messages().await.unwrap().is_empty());
+        assert!(!ch.idle_active());
+        assert_eq!(
+            ch.streaming_mode(),
+            StreamingMode::Polling { interval_ms: 30000 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_email_oauth_store_does_not_require_password() {
+        let config = EmailConfig {
+            username: "user@gmail.com".into(),
+            auth_method: EmailAuthMethod::XOAuth2,
+            oauth_provider: Some("gmail".into()),
+            ..Default::default()
+        };
+        let mut ch = EmailChannel::new(config, Box::new(MockSmtp), Box::new(MockImap));
+        ch.connect().await.unwrap();
+        assert!(ch.is_connected());
+    }
+
+    #[test]
+    fn test_email_config_defaults_from_toml() {
+        let config: EmailConfig = toml::from_str(
+            r#"
+            imap_host = "imap.example.com"
+            imap_port = 993
+            smtp_host = "smtp.example.com"
+            smtp_port = 587
+            username = "bot@example.com"
+            from_address = "bot@example.com"
+            allowed_senders = []
+            "#,
+        )
+        .unwrap();
+        assert!(config.idle);
+        assert_eq!(config.poll_interval_secs, 30);
+        assert_eq!(config.attachments_dir, PathBuf::from(".rustant/inbox"));
+        assert_eq!(config.max_attachment_bytes, 10 * 1024 * 1024);
+        assert!(config.oauth_provider.is_none());
+    }
 }
@@ -0,0 +1,628 @@
+//! Minimal RFC 5322 / MIME parsing for the email channel.
+//!
+//! Handles what the channel needs from a raw message: threading headers
+//! (Message-ID, In-Reply-To, References), RFC 2047 encoded subjects and names,
+//! nested multiparts, base64 / quoted-printable bodies, attachments, and a
+//! readable text body for HTML-only mail.
+
+use base64::Engine;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// A parsed email message.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedEmail {
+    /// Message-ID including angle brackets, if the message has one.
+    pub message_id: Option<String>,
+    pub in_reply_to: Option<String>,
+    /// Ancestor Message-IDs, oldest first.
+    pub references: Vec<String>,
+    /// Sender address (without display name).
+    pub from: String,
+    /// Sender display name, if given.
+    pub from_name: Option<String>,
+    pub subject: String,
+    /// Plain-text body; extracted from HTML when there is no text part.
+    pub text: String,
+    pub attachments: Vec<EmailAttachment>,
+}
+
+/// A decoded attachment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailAttachment {
+    pub filename: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Parse a raw RFC 822 message.
+pub fn parse_message(raw: &[u8]) -> ParsedEmail {
+    let (head, body) = split_head_body(raw);
+    let headers = parse_headers(&String::from_utf8_lossy(head));
+
+    let (from_name, from) = header(&headers, "from")
+        .map(|v| parse_address(&decode_encoded_words(v)))
+        .unwrap_or_default();
+    let mut parsed = ParsedEmail {
+        message_id: header(&headers, "message-id")
+            .and_then(|v| parse_msg_ids(v).into_iter().next()),
+        in_reply_to: header(&headers, "in-reply-to")
+            .and_then(|v| parse_msg_ids(v).into_iter().next()),
+        references: header(&headers, "references")
+            .map(parse_msg_ids)
+            .unwrap_or_default(),
+        from,
+        from_name,
+        subject: header(&headers, "subject")
+            .map(decode_encoded_words)
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let mut bodies = Bodies::default();
+    walk_part(&headers, body, &mut bodies, &mut parsed.attachments);
+    parsed.text = match (bodies.plain, bodies.html) {
+        (Some(plain), _) if !plain.trim().is_empty() => plain.trim().to_string(),
+        (_, Some(html)) => html_to_text(&html),
+        (plain, None) => plain.unwrap_or_default().trim().to_string(),
+    };
+    parsed
+}
+
+/// Text bodies found while walking the MIME tree (first of each kind wins).
+#[derive(Default)]
+struct Bodies {
+    plain: Option<String>,
+    html: Option<String>,
+}
+
+fn walk_part(
+    headers: &[(String, String)],
+    body: &[u8],
+    bodies: &mut Bodies,
+    attachments: &mut Vec<EmailAttachment>,
+) {
+    let (mime, params) = header(headers, "content-type")
+        .map(parse_content_type)
+        .unwrap_or_else(|| ("text/plain".to_string(), HashMap::new()));
+
+    if mime.starts_with("multipart/") {
+        if let Some(boundary) = params.get("boundary") {
+            for part in split_multipart(body, boundary) {
+                let (part_head, part_body) = split_head_body(part);
+                let part_headers = parse_headers(&String::from_utf8_lossy(part_head));
+                walk_part(&part_headers, part_body, bodies, attachments);
+            }
+        }
+        return;
+    }
+
+    let encoding = header(headers, "content-transfer-encoding")
+        .unwrap_or("7bit")
+        .trim()
+        .to_ascii_lowercase();
+    let data = decode_transfer(body, &encoding);
+    let disposition = header(headers, "content-disposition").map(parse_content_type);
+    let filename = disposition
+        .as_ref()
+        .and_then(|(_, p)| p.get("filename").cloned())
+        .or_else(|| params.get("name").cloned());
+    let is_attachment = disposition
+        .as_ref()
+        .is_some_and(|(kind, _)| kind == "attachment")
+        || filename.is_some()
+        || !(mime.starts_with("text/plain") || mime.starts_with("text/html"));
+
+    if is_attachment {
+        let fallback = if mime == "message/rfc822" {
+            "message.eml".to_string()
+        } else {
+            format!("attachment-{}", attachments.len() + 1)
+        };
+        attachments.push(EmailAttachment {
+            filename: filename.unwrap_or(fallback),
+            content_type: mime,
+            data,
+        });
+        return;
+    }
+
+    let charset = params.get("charset").map(String::as_str).unwrap_or("utf-8");
+    let text = decode_charset(&data, charset);
+    let slot = if mime == "text/html" {
+        &mut bodies.html
+    } else {
+        &mut bodies.plain
+    };
+    if slot.is_none() {
+        *slot = Some(text);
+    }
+}
+
+fn split_head_body(raw: &[u8]) -> (&[u8], &[u8]) {
+    for (sep, len) in [(&b"\r\n\r\n"[..], 4), (&b"\n\n"[..], 2)] {
+        if let Some(pos) = raw.windows(len).position(|w| w == sep) {
+            return (&raw[..pos], &raw[pos + len..]);
+        }
+    }
+    (raw, &[])
+}
+
+/// Parse header lines, unfolding continuations. Names are lowercased.
+fn parse_headers(head: &str) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    headers
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// Parse `type/subtype; key=value; ...` (also used for Content-Disposition).
+///
+/// Supports quoted values and RFC 2231 `key*=charset''percent-encoded` values.
+fn parse_content_type(value: &str) -> (String, HashMap<String, String>) {
+    let mut parts = split_params(value).into_iter();
+    let kind = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+    let mut params = HashMap::new();
+    for part in parts {
+        let Some((key, raw)) = part.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let raw = raw.trim();
+        if let Some(key) = key.strip_suffix('*') {
+            let encoded = raw.splitn(3, '\'').nth(2).unwrap_or(raw);
+            params.insert(key.to_string(), percent_decode(encoded));
+        } else {
+            let unquoted = raw.trim_matches('"').replace("\\\"", "\"");
+            params.insert(key, decode_encoded_words(&unquoted));
+        }
+    }
+    (kind, params)
+}
+
+/// Split on `;` outside quotes.
+fn split_params(value: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in value.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ';' if !quoted => parts.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = s
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Split a multipart body into its parts (without the boundary lines).
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let delimiter = delimiter.as_bytes();
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut line_start = 0;
+    while line_start < body.len() {
+        let line_end = body[line_start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|p| line_start + p + 1)
+            .unwrap_or(body.len());
+        let line = trim_line_end(&body[line_start..line_end]);
+        if line.starts_with(delimiter) {
+            if let Some(s) = start {
+                // Drop the line break that belongs to the boundary.
+                parts.push(trim_line_end(&body[s..line_start]));
+            }
+            if line[delimiter.len()..].starts_with(b"--") {
+                return parts;
+            }
+            start = Some(line_end);
+        }
+        line_start = line_end;
+    }
+    if let Some(s) = start {
+        parts.push(&body[s..]);
+    }
+    parts
+}
+
+fn trim_line_end(line: &[u8]) -> &[u8] {
+    let mut end = line.len();
+    while end > 0 && matches!(line[end - 1], b'\r' | b'\n') {
+        end -= 1;
+    }
+    &line[..end]
+}
+
+fn decode_transfer(body: &[u8], encoding: &str) -> Vec<u8> {
+    match encoding {
+        "base64" => decode_base64(body),
+        "quoted-printable" => decode_quoted_printable(body, false),
+        _ => body.to_vec(),
+    }
+}
+
+fn decode_base64(data: &[u8]) -> Vec<u8> {
+    let cleaned: Vec<u8> = data
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    let engine = base64::engine::GeneralPurpose::new(
+        &base64::alphabet::STANDARD,
+        base64::engine::GeneralPurposeConfig::new()
+            .with_decode_padding_mode(base64::engine::DecodePaddingMode::Indifferent),
+    );
+    engine.decode(&cleaned).unwrap_or_default()
+}
+
+/// Decode quoted-printable; `header` mode (RFC 2047 "Q") also maps `_` to space.
+fn decode_quoted_printable(data: &[u8], header: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        match data[i] {
+            b'=' => {
+                let rest = &data[i + 1..];
+                if rest.starts_with(b"\r\n") {
+                    i += 3;
+                } else if rest.starts_with(b"\n") {
+                    i += 2;
+                } else if let Some(byte) = rest
+                    .get(..2)
+                    .and_then(|h| std::str::from_utf8(h).ok())
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+                {
+                    out.push(byte);
+                    i += 3;
+                } else {
+                    out.push(b'=');
+                    i += 1;
+                }
+            }
+            b'_' if header => {
+                out.push(b' ');
+                i += 1;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn decode_charset(data: &[u8], charset: &str) -> String {
+    match charset.trim().to_ascii_lowercase().as_str() {
+        "iso-8859-1" | "latin1" | "latin-1" | "windows-1252" | "cp1252" => {
+            data.iter().map(|&b| b as char).collect()
+        }
+        _ => String::from_utf8_lossy(data).into_owned(),
+    }
+}
+
+static ENCODED_WORD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"=\?([^?]+)\?([bBqQ])\?([^?]*)\?=").expect("valid encoded-word regex")
+});
+
+/// Decode RFC 2047 encoded words (`=?utf-8?B?...?=`), dropping the whitespace
+/// between adjacent encoded words.
+pub fn decode_encoded_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    let mut previous_was_word = false;
+    for caps in ENCODED_WORD.captures_iter(value) {
+        let m = caps.get(0).expect("match");
+        let between = &value[last..m.start()];
+        if !(previous_was_word && between.trim().is_empty()) {
+            out.push_str(between);
+        }
+        let text = caps[3].as_bytes();
+        let bytes = if caps[2].eq_ignore_ascii_case("b") {
+            decode_base64(text)
+        } else {
+            decode_quoted_printable(text, true)
+        };
+        out.push_str(&decode_charset(&bytes, &caps[1]));
+        last = m.end();
+        previous_was_word = true;
+    }
+    out.push_str(&value[last..]);
+    out
+}
+
+static MSG_ID: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<[^<>\s]+>").expect("valid message-id regex"));
+
+/// Extract `<id@host>` tokens from a Message-ID / References header.
+pub fn parse_msg_ids(value: &str) -> Vec<String> {
+    let ids: Vec<String> = MSG_ID
+        .find_iter(value)
+        .map(|m| m.as_str().to_string())
+        .collect();
+    if ids.is_empty() && !value.trim().is_empty() && !value.contains(char::is_whitespace) {
+        return vec![format!("<{}>", value.trim().trim_matches(['<', '>']))];
+    }
+    ids
+}
+
+/// Split `"Name" <addr@host>` into display name and address.
+pub fn parse_address(value: &str) -> (Option<String>, String) {
+    if let (Some(open), Some(close)) = (value.rfind('<'), value.rfind('>'))
+        && open < close
+    {
+        let address = value[open + 1..close].trim().to_string();
+        let name = value[..open].trim().trim_matches('"').trim();
+        let name = (!name.is_empty()).then(|| name.to_string());
+        return (name, address);
+    }
+    (None, value.trim().to_string())
+}
+
+static DROPPED_BLOCKS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)<(script|style|head|title)\b[^>]*>.*?</(script|style|head|title)\s*>|<!--.*?-->",
+    )
+    .expect("valid html block regex")
+});
+static LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<a\b[^>]*?href\s*=\s*["']([^"']*)["'][^>]*>(.*?)</a\s*>"#)
+        .expect("valid link regex")
+});
+static LINE_BREAK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)<br\s*/?>|</(p|div|tr|h[1-6]|blockquote|ul|ol|table)\s*>")
+        .expect("valid line break regex")
+});
+static LIST_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<li\b[^>]*>").expect("valid list item regex"));
+static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").expect("valid tag regex"));
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").expect("valid entity regex")
+});
+
+/// Convert an HTML body to readable plain text.
+///
+/// Drops scripts, styles and comments, turns block ends and `<br>` into line
+/// breaks and list items into `- ` bullets, keeps link targets as
+/// `text (url)`, and decodes common entities.
+pub fn html_to_text(html: &str) -> String {
+    let text = DROPPED_BLOCKS.replace_all(html, "");
+    let text = LINK.replace_all(&text, |caps: &regex::Captures| {
+        let url = caps[1].trim();
+        let label = TAG.replace_all(&caps[2], "").trim().to_string();
+        if label.is_empty() || label == url || url.starts_with("mailto:") {
+            if label.is_empty() {
+                url.to_string()
+            } else {
+                label
+            }
+        } else {
+            format!("{} ({})", label, url)
+        }
+    });
+    let text = LINE_BREAK.replace_all(&text, "\n");
+    let text = LIST_ITEM.replace_all(&text, "\n- ");
+    let text = TAG.replace_all(&text, "");
+    let text = ENTITY.replace_all(&text, |caps: &regex::Captures| decode_entity(&caps[1]));
+
+    let mut out = String::new();
+    let mut blank_run = 0;
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            blank_run += 1;
+            if blank_run > 1 || out.is_empty() {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out.trim().to_string()
+}
+
+fn decode_entity(entity: &str) -> String {
+    let code = if let Some(hex) = entity
+        .strip_prefix("#x")
+        .or_else(|| entity.strip_prefix("#X"))
+    {
+        u32::from_str_radix(hex, 16).ok()
+    } else if let Some(dec) = entity.strip_prefix('#') {
+        dec.parse().ok()
+    } else {
+        None
+    };
+    if let Some(c) = code.and_then(char::from_u32) {
+        return c.to_string();
+    }
+    match entity {
+        "nbsp" => " ",
+        "amp" => "&",
+        "lt" => "<",
+        "gt" => ">",
+        "quot" => "\"",
+        "apos" => "'",
+        "mdash" => "—",
+        "ndash" => "–",
+        "hellip" => "…",
+        "rsquo" | "lsquo" => "'",
+        "rdquo" | "ldquo" => "\"",
+        "copy" => "©",
+        _ => return format!("&{};", entity),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_message_with_threading_headers() {
+        let raw = b"From: \"Alice Smith\" <alice@example.com>\r\n\
+Subject: =?utf-8?B?UmU6IGNhZsOp?= plans\r\n\
+Message-ID: <m2@example.com>\r\n\
+In-Reply-To: <m1@example.com>\r\n\
+References: <m0@example.com>\r\n <m1@example.com>\r\n\
+\r\n\
+See you there.\r\n";
+        let parsed = parse_message(raw);
+        assert_eq!(parsed.from, "alice@example.com");
+        assert_eq!(parsed.from_name.as_deref(), Some("Alice Smith"));
+        assert_eq!(parsed.subject, "Re: caf\u{e9} plans");
+        assert_eq!(parsed.message_id.as_deref(), Some("<m2@example.com>"));
+        assert_eq!(parsed.in_reply_to.as_deref(), Some("<m1@example.com>"));
+        assert_eq!(
+            parsed.references,
+            vec!["<m0@example.com>", "<m1@example.com>"]
+        );
+        assert_eq!(parsed.text, "See you there.");
+        assert!(parsed.attachments.is_empty());
+    }
+
+    #[test]
+    fn test_parse_multipart_with_attachment_and_alternative() {
+        let raw = b"From: bob@example.com\r\n\
+Subject: Report\r\n\
+Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+\r\n\
+preamble\r\n\
+--outer\r\n\
+Content-Type: multipart/alternative; boundary=inner\r\n\
+\r\n\
+--inner\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+Numbers are =E2=82=AC5 this =\r\n\
+week.\r\n\
+--inner\r\n\
+Content-Type: text/html\r\n\
+\r\n\
+<p>Numbers are &euro;5</p>\r\n\
+--inner--\r\n\
+--outer\r\n\
+Content-Type: application/pdf; name=\"report.pdf\"\r\n\
+Content-Disposition: attachment; filename=\"report.pdf\"\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+JVBERi0x\r\n\
+LjQ=\r\n\
+--outer\r\n\
+Content-Type: text/csv\r\n\
+Content-Disposition: attachment; filename*=utf-8''caf%C3%A9.csv\r\n\
+\r\n\
+a,b\r\n\
+--outer--\r\n";
+        let parsed = parse_message(raw);
+        assert_eq!(parsed.text, "Numbers are \u{20ac}5 this week.");
+        assert_eq!(parsed.attachments.len(), 2);
+        assert_eq!(parsed.attachments[0].filename, "report.pdf");
+        assert_eq!(parsed.attachments[0].content_type, "application/pdf");
+        assert_eq!(parsed.attachments[0].data, b"%PDF-1.4");
+        assert_eq!(parsed.attachments[1].filename, "caf\u{e9}.csv");
+        assert_eq!(parsed.attachments[1].data, b"a,b");
+    }
+
+    #[test]
+    fn test_html_only_message_is_converted() {
+        let raw = b"From: news@example.com\n\
+Content-Type: text/html; charset=iso-8859-1\n\
+\n\
+<html><head><style>p{color:red}</style></head><body>\n\
+<h1>Weekly&nbsp;update</h1><p>Caf\xe9 &amp; more<br>second line</p>\n\
+<ul><li>One</li><li>Two</li></ul>\n\
+<p><a href=\"https://example.com/x\">Read more</a></p><script>alert(1)</script>\n\
+</body></html>";
+        let parsed = parse_message(raw);
+        assert_eq!(
+            parsed.text,
+            "Weekly update\nCaf\u{e9} & more\nsecond line\n\n- One\n- Two\n\nRead more (https://example.com/x)"
+        );
+    }
+
+    #[test]
+    fn test_html_to_text_entities_and_blank_lines() {
+        assert_eq!(
+            html_to_text("a&#39;b &#x41; &lt;c&gt; &bogus;"),
+            "a'b A <c> &bogus;"
+        );
+        assert_eq!(html_to_text("<p>one</p>\n\n\n\n<p>two</p>"), "one\n\ntwo");
+        assert_eq!(
+            html_to_text("<a href=\"https://x.io\">https://x.io</a>"),
+            "https://x.io"
+        );
+    }
+
+    #[test]
+    fn test_parse_msg_ids_and_address() {
+        assert_eq!(parse_msg_ids("<a@b> <c@d>"), vec!["<a@b>", "<c@d>"]);
+        assert_eq!(parse_msg_ids("bare@id"), vec!["<bare@id>"]);
+        assert!(parse_msg_ids("").is_empty());
+        assert_eq!(
+            parse_address("carol@example.com"),
+            (None, "carol@example.com".into())
+        );
+        assert_eq!(
+            parse_address("Carol <carol@example.com>"),
+            (Some("Carol".into()), "carol@example.com".into())
+        );
+    }
+
+    #[test]
+    fn test_encoded_words_q_and_adjacent() {
+        assert_eq!(
+            decode_encoded_words("=?ISO-8859-1?Q?Andr=E9_Pirard?="),
+            "Andr\u{e9} Pirard"
+        );
+        assert_eq!(
+            decode_encoded_words("=?utf-8?Q?a?= =?utf-8?Q?b?= c"),
+            "ab c"
+        );
+        assert_eq!(decode_encoded_words("plain"), "plain");
+    }
+}
@@ -1,13 +1,23 @@
 //! Channel manager — registers, connects, polls, and broadcasts across channels.
 //!
-//! Optionally holds a [`PairingManager`] for device-pairing enforcement.
+//! Optionally holds a [`PairingManager`] for device-pairing enforcement, and an
+//! outgoing queue for messages produced by background components (e.g. digests)
+//! that are flushed by the channel loop.
 
 use super::{
-    Channel, ChannelCapabilities, ChannelMessage, ChannelStatus, MessageId, StreamingMode,
+    Channel, ChannelCapabilities, ChannelMessage, ChannelStatus, ChannelType, MessageId,
+    StreamingMode,
 };
 use crate::error::{ChannelError, RustantError};
 use crate::pairing::PairingManager;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+
+/// A message waiting in the outgoing queue for a named channel.
+#[derive(Debug, Clone)]
+pub struct OutgoingMessage {
+    pub channel_name: String,
+    pub message: ChannelMessage,
+}
 
 /// Manages a set of registered channels.
 ///
@@ -16,6 +26,7 @@ use std::collections::HashMap;
 pub struct ChannelManager {
     channels: HashMap<String, Box<dyn Channel>>,
     pairing: Option<PairingManager>,
+    outgoing: VecDeque<OutgoingMessage>,
 }
 
 impl ChannelManager {
@@ -23,6 +34,7 @@ impl ChannelManager {
         Self {
             channels: HashMap::new(),
             pairing: None,
+            outgoing: VecDeque::new(),
         }
     }
 
@@ -136,6 +148,36 @@ impl ChannelManager {
         channel.send_message(msg).await
     }
 
+    /// Get the channel type of a registered channel.
+    pub fn channel_type(&self, name: &str) -> Option<ChannelType> {
+        self.channels.get(name).map(|c| c.channel_type())
+    }
+
+    /// Queue a message for delivery on the next [`flush_outgoing`](Self::flush_outgoing).
+    pub fn enqueue(&mut self, channel_name: impl Into<String>, msg: ChannelMessage) {
+        self.outgoing.push_back(OutgoingMessage {
+            channel_name: channel_name.into(),
+            message: msg,
+        });
+    }
+
+    /// Number of messages waiting in the outgoing queue.
+    pub fn outgoing_len(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    /// Send all queued messages in order, returning each with its send result.
+    pub async fn flush_outgoing(
+        &mut self,
+    ) -> Vec<(OutgoingMessage, Result<MessageId, RustantError>)> {
+        let mut results = Vec::with_capacity(self.outgoing.len());
+        while let Some(out) = self.outgoing.pop_front() {
+            let result = self.send_to(&out.channel_name, out.message.clone()).await;
+            results.push((out, result));
+        }
+        results
+    }
+
     /// Get number of connected channels.
     pub fn connected_count(&self) -> usize {
         self.channels.values().filter(|c| c.is_connected()).count()
@@ -409,6 +451,33 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_manager_outgoing_queue_flush() {
+        let mut mgr = ChannelManager::new();
+        let channel = MockChannel::new("tg", ChannelType::Telegram);
+        let sent = channel.sent.clone();
+        mgr.register(Box::new(channel));
+        mgr.connect_all().await;
+
+        let sender = ChannelUser::new("bot", ChannelType::Telegram);
+        mgr.enqueue(
+            "tg",
+            ChannelMessage::text(ChannelType::Telegram, "chat", sender.clone(), "first"),
+        );
+        mgr.enqueue(
+            "missing",
+            ChannelMessage::text(ChannelType::Telegram, "chat", sender, "second"),
+        );
+        assert_eq!(mgr.outgoing_len(), 2);
+
+        let results = mgr.flush_outgoing().await;
+        assert_eq!(mgr.outgoing_len(), 0);
+        assert!(results[0].1.is_ok());
+        assert!(results[1].1.is_err());
+        assert_eq!(sent.lock().unwrap().len(), 1);
+        assert_eq!(mgr.channel_type("tg"), Some(ChannelType::Telegram));
+    }
+
     #[tokio::test]
     async fn test_manager_send_to_not_found() {
         let mgr = ChannelManager::new();
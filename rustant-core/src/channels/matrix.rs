@@ -1,40 +1,107 @@
 //! Matrix Client-Server API channel implementation.
 //!
 //! Connects to a Matrix homeserver via the Client-Server API using reqwest.
-//! In tests, a trait abstraction provides mock implementations.
+//! Incoming messages come from incremental `/sync` long-polling. The
+//! `next_batch` token is persisted after every sync, so a restart resumes
+//! where the last run stopped instead of replaying room history. Outgoing
+//! markdown is sent with an HTML `formatted_body`, room aliases are resolved
+//! to room ids, and threads map onto `m.thread` relations.
+//!
+//! End-to-end encrypted rooms are detected but not yet supported: sending to
+//! one fails with [`ChannelError::EncryptionNotSupported`] and encrypted
+//! events are skipped with a warning. In tests, a trait abstraction provides
+//! mock implementations.
 
 use super::{
     Channel, ChannelCapabilities, ChannelMessage, ChannelStatus, ChannelType, ChannelUser,
-    MessageId, StreamingMode,
+    MessageId, StreamingMode, ThreadId,
 };
 use crate::error::{ChannelError, RustantError};
+use crate::secret_ref::{SecretRef, SecretResolver};
 use async_trait::async_trait;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
 
 /// Configuration for a Matrix channel.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MatrixConfig {
     pub homeserver_url: String,
-    pub access_token: String,
+    /// Access token.
+    /// Supports `SecretRef` format: `"keychain:channel:matrix:access_token"`,
+    /// `"env:MATRIX_ACCESS_TOKEN"`, or inline plaintext (deprecated).
+    pub access_token: SecretRef,
+    /// The bot's user id. Looked up from the access token when empty.
     pub user_id: String,
+    /// Rooms to listen in, as ids (`!abc:example.org`) or aliases
+    /// (`#ops:example.org`). Empty listens in every joined room.
     pub room_ids: Vec<String>,
+    /// File holding the persisted sync token. Defaults to
+    /// `matrix/<channel name>.json` in the rustant data directory.
+    #[serde(default)]
+    pub sync_state_path: Option<PathBuf>,
+}
+
+impl MatrixConfig {
+    /// Resolve the access token from its `SecretRef` to an actual token string.
+    pub fn resolve_access_token(&self) -> Result<String, crate::secret_ref::SecretResolveError> {
+        let store = crate::credentials::configured_store();
+        SecretResolver::resolve(&self.access_token, store.as_ref())
+    }
 }
 
 /// Trait for Matrix API interactions.
 #[async_trait]
 pub trait MatrixHttpClient: Send + Sync {
-    async fn send_message(&self, room_id: &str, text: &str) -> Result<String, String>;
-    async fn sync(&self, since: Option<&str>) -> Result<Vec<MatrixEvent>, String>;
+    /// Send an `m.room.message` event with `content`. Returns the event id.
+    async fn send_message(
+        &self,
+        room_id: &str,
+        content: serde_json::Value,
+    ) -> Result<String, String>;
+    /// Run one `/sync`, long-polling for new events when `since` is set.
+    async fn sync(&self, since: Option<&str>) -> Result<MatrixSync, String>;
+    /// Verify the access token. Returns the user id it belongs to.
     async fn login(&self) -> Result<String, String>;
+    /// Resolve a room alias (`#room:server`) to its room id.
+    async fn resolve_alias(&self, alias: &str) -> Result<String, String>;
+    /// Whether a room has end-to-end encryption enabled.
+    async fn is_encrypted(&self, room_id: &str) -> Result<bool, String>;
 }
 
 /// A Matrix room event.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct MatrixEvent {
     pub event_id: String,
     pub room_id: String,
     pub sender: String,
     pub body: String,
+    /// Root event of the thread this message belongs to (`m.thread`).
+    pub thread_root: Option<String>,
+    /// Event this message replies to (`m.in_reply_to`).
+    pub in_reply_to: Option<String>,
+    /// An `m.room.encrypted` event, whose body can't be read.
+    pub encrypted: bool,
+}
+
+/// The result of one `/sync` call.
+#[derive(Debug, Clone, Default)]
+pub struct MatrixSync {
+    /// Token to pass as `since` on the next sync.
+    pub next_batch: String,
+    pub events: Vec<MatrixEvent>,
+    /// Rooms whose state in this batch enables end-to-end encryption.
+    pub encrypted_rooms: Vec<String>,
+}
+
+/// Sync position persisted between runs.
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncState {
+    user_id: String,
+    next_batch: String,
 }
 
 /// Matrix channel.
@@ -43,17 +110,26 @@ pub struct MatrixChannel {
     status: ChannelStatus,
     http_client: Box<dyn MatrixHttpClient>,
     name: String,
-    next_batch: Option<String>,
+    user_id: String,
+    /// Resolved ids of the rooms listened in; empty means every joined room.
+    rooms: HashSet<String>,
+    next_batch: Mutex<Option<String>>,
+    aliases: Mutex<HashMap<String, String>>,
+    encryption: Mutex<HashMap<String, bool>>,
 }
 
 impl MatrixChannel {
     pub fn new(config: MatrixConfig, http_client: Box<dyn MatrixHttpClient>) -> Self {
         Self {
+            user_id: config.user_id.clone(),
             config,
             status: ChannelStatus::Disconnected,
             http_client,
             name: "matrix".to_string(),
-            next_batch: None,
+            rooms: HashSet::new(),
+            next_batch: Mutex::new(None),
+            aliases: Mutex::new(HashMap::new()),
+            encryption: Mutex::new(HashMap::new()),
         }
     }
 
@@ -61,6 +137,180 @@ impl MatrixChannel {
         self.name = name.into();
         self
     }
+
+    /// The token the next sync resumes from.
+    pub fn sync_token(&self) -> Option<String> {
+        self.next_batch
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Rooms seen so far that use end-to-end encryption, which this channel
+    /// can't read or send to yet.
+    pub fn encrypted_rooms(&self) -> Vec<String> {
+        let encryption = self.encryption.lock().unwrap_or_else(|e| e.into_inner());
+        let mut rooms: Vec<String> = encryption
+            .iter()
+            .filter(|(_, encrypted)| **encrypted)
+            .map(|(room, _)| room.clone())
+            .collect();
+        rooms.sort();
+        rooms
+    }
+
+    fn state_path(&self) -> Option<PathBuf> {
+        self.config.sync_state_path.clone().or_else(|| {
+            let file: String = self
+                .name
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            directories::ProjectDirs::from("dev", "rustant", "rustant")
+                .map(|d| d.data_dir().join("matrix").join(format!("{}.json", file)))
+        })
+    }
+
+    /// Load the persisted sync token, ignoring one saved for another account.
+    fn load_sync_token(&self) -> Option<String> {
+        let path = self.state_path()?;
+        let json = std::fs::read_to_string(&path).ok()?;
+        let state: SyncState = serde_json::from_str(&json)
+            .inspect_err(|e| {
+                tracing::warn!(path = %path.display(), "Ignoring corrupt Matrix sync state: {}", e)
+            })
+            .ok()?;
+        (state.user_id == self.user_id && !state.next_batch.is_empty()).then_some(state.next_batch)
+    }
+
+    fn store_sync_token(&self, next_batch: &str) {
+        let Some(path) = self.state_path() else {
+            return;
+        };
+        let state = SyncState {
+            user_id: self.user_id.clone(),
+            next_batch: next_batch.to_string(),
+        };
+        let result = (|| -> std::io::Result<()> {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let tmp = path.with_extension("json.tmp");
+            std::fs::write(&tmp, serde_json::to_string_pretty(&state)?)?;
+            std::fs::rename(&tmp, &path)
+        })();
+        if let Err(e) = result {
+            tracing::warn!(
+                channel = %self.name,
+                path = %path.display(),
+                "Failed to persist Matrix sync token: {}",
+                e
+            );
+        }
+    }
+
+    /// Resolve a room alias to its id; room ids are returned unchanged.
+    async fn resolve_room(&self, room: &str) -> Result<String, String> {
+        if !room.starts_with('#') {
+            return Ok(room.to_string());
+        }
+        if let Some(id) = self
+            .aliases
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(room)
+        {
+            return Ok(id.clone());
+        }
+        let id = self
+            .http_client
+            .resolve_alias(room)
+            .await
+            .map_err(|e| format!("cannot resolve room alias '{}': {}", room, e))?;
+        self.aliases
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(room.to_string(), id.clone());
+        Ok(id)
+    }
+
+    async fn room_encrypted(&self, room_id: &str) -> Result<bool, String> {
+        if let Some(encrypted) = self
+            .encryption
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(room_id)
+        {
+            return Ok(*encrypted);
+        }
+        let encrypted = self.http_client.is_encrypted(room_id).await?;
+        if encrypted {
+            self.mark_encrypted(room_id);
+        } else {
+            self.encryption
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(room_id.to_string(), false);
+        }
+        Ok(encrypted)
+    }
+
+    /// Record that a room is encrypted, warning the first time.
+    fn mark_encrypted(&self, room_id: &str) {
+        let previous = self
+            .encryption
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(room_id.to_string(), true);
+        if previous != Some(true) {
+            tracing::warn!(
+                channel = %self.name,
+                room = %room_id,
+                "Matrix room is end-to-end encrypted; encryption not yet supported, its messages are skipped"
+            );
+        }
+    }
+
+    fn encryption_error(&self, room: impl Into<String>) -> RustantError {
+        RustantError::Channel(ChannelError::EncryptionNotSupported {
+            name: self.name.clone(),
+            room: room.into(),
+        })
+    }
+
+    fn connection_error(&self, message: String) -> RustantError {
+        RustantError::Channel(ChannelError::ConnectionFailed {
+            name: self.name.clone(),
+            message,
+        })
+    }
+
+    fn send_error(&self, message: String) -> RustantError {
+        RustantError::Channel(ChannelError::SendFailed {
+            name: self.name.clone(),
+            message,
+        })
+    }
+
+    fn to_channel_message(event: MatrixEvent) -> ChannelMessage {
+        let sender = ChannelUser::new(&event.sender, ChannelType::Matrix);
+        let mut msg =
+            ChannelMessage::text(ChannelType::Matrix, &event.room_id, sender, &event.body);
+        msg.id = MessageId::new(event.event_id);
+        if let Some(root) = event.thread_root {
+            msg = msg.with_thread(ThreadId::new(root));
+        }
+        if let Some(parent) = event.in_reply_to {
+            msg = msg.with_reply_to(MessageId::new(parent));
+        }
+        msg
+    }
 }
 
 #[async_trait]
@@ -79,12 +329,44 @@ impl Channel for MatrixChannel {
                 name: self.name.clone(),
             }));
         }
-        self.http_client.login().await.map_err(|e| {
-            RustantError::Channel(ChannelError::ConnectionFailed {
-                name: self.name.clone(),
-                message: e,
-            })
-        })?;
+        let user_id = self
+            .http_client
+            .login()
+            .await
+            .map_err(|e| self.connection_error(e))?;
+        if self.config.user_id.is_empty() {
+            self.user_id = user_id;
+        }
+
+        let mut rooms = HashSet::new();
+        let mut encrypted = Vec::new();
+        for room in &self.config.room_ids {
+            let id = self
+                .resolve_room(room)
+                .await
+                .map_err(|e| self.connection_error(e))?;
+            if self
+                .room_encrypted(&id)
+                .await
+                .map_err(|e| self.connection_error(e))?
+            {
+                encrypted.push(room.clone());
+            }
+            rooms.insert(id);
+        }
+        if !rooms.is_empty() && encrypted.len() == rooms.len() {
+            self.status = ChannelStatus::Failed;
+            return Err(self.encryption_error(encrypted.join(", ")));
+        }
+        self.rooms = rooms;
+
+        let stored = self.load_sync_token();
+        let mut next_batch = self.next_batch.lock().unwrap_or_else(|e| e.into_inner());
+        if next_batch.is_none() {
+            *next_batch = stored;
+        }
+        drop(next_batch);
+
         self.status = ChannelStatus::Connected;
         Ok(())
     }
@@ -96,42 +378,30 @@ impl Channel for MatrixChannel {
 
     async fn send_message(&self, msg: ChannelMessage) -> Result<MessageId, RustantError> {
         let text = msg.content.as_text().unwrap_or("");
+        let room_id = self
+            .resolve_room(&msg.channel_id)
+            .await
+            .map_err(|e| self.send_error(e))?;
+        if self
+            .room_encrypted(&room_id)
+            .await
+            .map_err(|e| self.send_error(e))?
+        {
+            return Err(self.encryption_error(msg.channel_id.clone()));
+        }
+
+        let content = message_content(text, msg.thread_id.as_ref(), msg.reply_to.as_ref());
         self.http_client
-            .send_message(&msg.channel_id, text)
+            .send_message(&room_id, content)
             .await
             .map(MessageId::new)
-            .map_err(|e| {
-                RustantError::Channel(ChannelError::SendFailed {
-                    name: self.name.clone(),
-                    message: e,
-                })
-            })
+            .map_err(|e| self.send_error(e))
     }
 
     async fn receive_messages(&self) -> Result<Vec<ChannelMessage>, RustantError> {
-        let events = self
-            .http_client
-            .sync(self.next_batch.as_deref())
+        self.receive_messages_since(None)
             .await
-            .map_err(|e| {
-                RustantError::Channel(ChannelError::ConnectionFailed {
-                    name: self.name.clone(),
-                    message: e,
-                })
-            })?;
-
-        let messages = events
-            .into_iter()
-            .filter(|e| {
-                self.config.room_ids.is_empty() || self.config.room_ids.contains(&e.room_id)
-            })
-            .map(|e| {
-                let sender = ChannelUser::new(&e.sender, ChannelType::Matrix);
-                ChannelMessage::text(ChannelType::Matrix, &e.room_id, sender, &e.body)
-            })
-            .collect();
-
-        Ok(messages)
+            .map(|(messages, _)| messages)
     }
 
     fn status(&self) -> ChannelStatus {
@@ -154,6 +424,386 @@ impl Channel for MatrixChannel {
     fn streaming_mode(&self) -> StreamingMode {
         StreamingMode::LongPolling
     }
+
+    /// Sync from `cursor`, or from the channel's own persisted token when
+    /// `cursor` is `None`. The new `next_batch` token is persisted and
+    /// returned as the cursor.
+    ///
+    /// Without any token, the first sync only establishes the position: the
+    /// recent room history it returns is not delivered as new messages.
+    async fn receive_messages_since(
+        &self,
+        cursor: Option<&str>,
+    ) -> Result<(Vec<ChannelMessage>, Option<String>), RustantError> {
+        let since = cursor.map(str::to_string).or_else(|| self.sync_token());
+        let sync = self
+            .http_client
+            .sync(since.as_deref())
+            .await
+            .map_err(|e| self.connection_error(e))?;
+
+        for room in &sync.encrypted_rooms {
+            self.mark_encrypted(room);
+        }
+        if !sync.next_batch.is_empty() {
+            *self.next_batch.lock().unwrap_or_else(|e| e.into_inner()) =
+                Some(sync.next_batch.clone());
+            self.store_sync_token(&sync.next_batch);
+        }
+        let next_cursor = (!sync.next_batch.is_empty())
+            .then_some(sync.next_batch)
+            .or(since.clone());
+        if since.is_none() {
+            return Ok((Vec::new(), next_cursor));
+        }
+
+        let mut messages = Vec::new();
+        for event in sync.events {
+            if (!self.rooms.is_empty() && !self.rooms.contains(&event.room_id))
+                || event.sender == self.user_id
+            {
+                continue;
+            }
+            if event.encrypted {
+                self.mark_encrypted(&event.room_id);
+                continue;
+            }
+            messages.push(Self::to_channel_message(event));
+        }
+        Ok((messages, next_cursor))
+    }
+}
+
+/// Build `m.room.message` content for `text`.
+///
+/// Markdown gets an HTML `formatted_body`. A thread id becomes an `m.thread`
+/// relation, with a reply fallback for clients without thread support, and a
+/// reply-to id becomes `m.in_reply_to`.
+pub fn message_content(
+    text: &str,
+    thread: Option<&ThreadId>,
+    reply_to: Option<&MessageId>,
+) -> serde_json::Value {
+    let mut content = json!({ "msgtype": "m.text", "body": text });
+    if let Some(html) = markdown_to_html(text) {
+        content["format"] = json!("org.matrix.custom.html");
+        content["formatted_body"] = json!(html);
+    }
+    match (thread, reply_to) {
+        (Some(thread), reply) => {
+            let parent = reply.map(|r| r.0.as_str()).unwrap_or(&thread.0);
+            content["m.relates_to"] = json!({
+                "rel_type": "m.thread",
+                "event_id": thread.0,
+                "is_falling_back": reply.is_none(),
+                "m.in_reply_to": { "event_id": parent },
+            });
+        }
+        (None, Some(reply)) => {
+            content["m.relates_to"] = json!({ "m.in_reply_to": { "event_id": reply.0 } });
+        }
+        (None, None) => {}
+    }
+    content
+}
+
+/// Extract messages, encrypted events and encryption state from a `/sync`
+/// response body.
+///
+/// Edits (`m.replace`) are skipped, since the original message was already
+/// delivered, and reply fallbacks quoting the parent message are stripped.
+pub fn parse_sync(body: &serde_json::Value) -> MatrixSync {
+    let mut sync = MatrixSync {
+        next_batch: body["next_batch"].as_str().unwrap_or("").to_string(),
+        ..Default::default()
+    };
+    let Some(rooms) = body["rooms"]["join"].as_object() else {
+        return sync;
+    };
+    for (room_id, room) in rooms {
+        let state = room["state"]["events"].as_array().into_iter().flatten();
+        let timeline = room["timeline"]["events"].as_array().into_iter().flatten();
+        for event in state.chain(timeline) {
+            let event_id = event["event_id"].as_str().unwrap_or("").to_string();
+            let sender = event["sender"].as_str().unwrap_or("").to_string();
+            match event["type"].as_str() {
+                Some("m.room.encryption") if !sync.encrypted_rooms.contains(room_id) => {
+                    sync.encrypted_rooms.push(room_id.clone());
+                }
+                Some("m.room.encryption") => {}
+                Some("m.room.encrypted") => sync.events.push(MatrixEvent {
+                    event_id,
+                    room_id: room_id.clone(),
+                    sender,
+                    encrypted: true,
+                    ..Default::default()
+                }),
+                Some("m.room.message") => {
+                    let content = &event["content"];
+                    let relates = &content["m.relates_to"];
+                    let rel_type = relates["rel_type"].as_str();
+                    let Some(text) = content["body"].as_str() else {
+                        continue;
+                    };
+                    if rel_type == Some("m.replace") {
+                        continue;
+                    }
+                    let thread_root = if rel_type == Some("m.thread") {
+                        relates["event_id"].as_str().map(str::to_string)
+                    } else {
+                        None
+                    };
+                    let in_reply_to = if relates["is_falling_back"].as_bool() == Some(true) {
+                        None
+                    } else {
+                        relates["m.in_reply_to"]["event_id"]
+                            .as_str()
+                            .map(str::to_string)
+                    };
+                    let text = if in_reply_to.is_some() {
+                        strip_reply_fallback(text)
+                    } else {
+                        text
+                    };
+                    sync.events.push(MatrixEvent {
+                        event_id,
+                        room_id: room_id.clone(),
+                        sender,
+                        body: text.to_string(),
+                        thread_root,
+                        in_reply_to,
+                        encrypted: false,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+    sync
+}
+
+/// Drop the `> <@user> quoted text` lines that replies prepend to their body.
+fn strip_reply_fallback(body: &str) -> &str {
+    if !body.starts_with("> ") {
+        return body;
+    }
+    let mut rest = body;
+    while rest.starts_with('>') {
+        rest = rest.split_once('\n').map(|(_, r)| r).unwrap_or("");
+    }
+    rest.strip_prefix('\n').unwrap_or(rest)
+}
+
+static MD_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\[([^\]\n]+)\]\(([^)\s]+)\)").expect("valid markdown link regex")
+});
+static MD_STRONG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\*\*([^*\n]+)\*\*|__([^_\n]+)__").expect("valid markdown strong regex")
+});
+static MD_EM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\*([^*\s][^*\n]*)\*|\b_([^_\s][^_\n]*)_\b").expect("valid markdown em regex")
+});
+static MD_STRIKE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"~~([^~\n]+)~~").expect("valid markdown strike regex"));
+static MD_ORDERED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\d+[.)]\s+").expect("valid ordered list regex"));
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render inline markdown (code spans, links, emphasis) in one line.
+fn inline_html(line: &str) -> String {
+    let parts: Vec<&str> = line.split('`').collect();
+    let mut out = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        if i % 2 == 1 && i + 1 < parts.len() {
+            out.push_str(&format!("<code>{}</code>", escape_html(part)));
+            continue;
+        }
+        if i % 2 == 1 {
+            // Unmatched backtick.
+            out.push('`');
+        }
+        let text = escape_html(part);
+        let text = MD_LINK.replace_all(&text, |caps: &regex::Captures| {
+            let url = &caps[2];
+            if url.starts_with("https://")
+                || url.starts_with("http://")
+                || url.starts_with("mailto:")
+            {
+                format!("<a href=\"{}\">{}</a>", url, &caps[1])
+            } else {
+                caps[0].to_string()
+            }
+        });
+        let text = MD_STRONG.replace_all(&text, |caps: &regex::Captures| {
+            let inner = caps
+                .get(1)
+                .or_else(|| caps.get(2))
+                .map_or("", |m| m.as_str());
+            format!("<strong>{}</strong>", inner)
+        });
+        let text = MD_EM.replace_all(&text, |caps: &regex::Captures| {
+            let inner = caps
+                .get(1)
+                .or_else(|| caps.get(2))
+                .map_or("", |m| m.as_str());
+            format!("<em>{}</em>", inner)
+        });
+        let text = MD_STRIKE.replace_all(&text, "<del>$1</del>");
+        out.push_str(&text);
+    }
+    out
+}
+
+/// Convert markdown to the HTML subset Matrix clients render.
+///
+/// Handles paragraphs, headings, bullet and numbered lists, block quotes,
+/// fenced code blocks, code spans, links, bold, italic and strikethrough.
+/// Returns `None` when `text` has no formatting, so plain messages are sent
+/// without a `formatted_body`.
+pub fn markdown_to_html(text: &str) -> Option<String> {
+    #[derive(Default)]
+    struct Blocks {
+        html: Vec<String>,
+        paragraph: Vec<String>,
+        quote: Vec<String>,
+        list: Option<(&'static str, Vec<String>)>,
+        formatted: bool,
+    }
+
+    impl Blocks {
+        fn inline(&mut self, line: &str) -> String {
+            let html = inline_html(line);
+            self.formatted |= html != escape_html(line);
+            html
+        }
+
+        fn flush_paragraph(&mut self) {
+            if !self.paragraph.is_empty() {
+                let lines = std::mem::take(&mut self.paragraph);
+                self.html.push(format!("<p>{}</p>", lines.join("<br>")));
+            }
+        }
+
+        fn flush_quote(&mut self) {
+            if !self.quote.is_empty() {
+                let lines = std::mem::take(&mut self.quote);
+                self.html
+                    .push(format!("<blockquote>{}</blockquote>", lines.join("<br>")));
+            }
+        }
+
+        fn flush_list(&mut self) {
+            if let Some((tag, items)) = self.list.take() {
+                let items: String = items.iter().map(|i| format!("<li>{}</li>", i)).collect();
+                self.html.push(format!("<{tag}>{items}</{tag}>"));
+            }
+        }
+
+        fn flush(&mut self) {
+            self.flush_paragraph();
+            self.flush_quote();
+            self.flush_list();
+        }
+
+        fn list_item(&mut self, tag: &'static str, item: &str) {
+            self.flush_paragraph();
+            self.flush_quote();
+            if self.list.as_ref().is_some_and(|(t, _)| *t != tag) {
+                self.flush_list();
+            }
+            let item = self.inline(item);
+            self.list.get_or_insert((tag, Vec::new())).1.push(item);
+            self.formatted = true;
+        }
+    }
+
+    let mut blocks = Blocks::default();
+    let mut code: Option<(String, Vec<&str>)> = None;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if let Some((lang, lines)) = &mut code {
+            if trimmed.starts_with("```") {
+                blocks
+                    .html
+                    .push(code_block(lang, &std::mem::take(lines).join("\n")));
+                code = None;
+            } else {
+                lines.push(line);
+            }
+            continue;
+        }
+
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            blocks.flush();
+            blocks.formatted = true;
+            code = Some((lang.trim().to_string(), Vec::new()));
+        } else if trimmed.is_empty() {
+            blocks.flush();
+        } else if let Some((level, heading)) = heading(trimmed) {
+            blocks.flush();
+            let heading = blocks.inline(heading);
+            blocks.html.push(format!("<h{level}>{heading}</h{level}>"));
+            blocks.formatted = true;
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|bullet| trimmed.strip_prefix(bullet))
+        {
+            blocks.list_item("ul", item);
+        } else if let Some(marker) = MD_ORDERED.find(trimmed) {
+            blocks.list_item("ol", &trimmed[marker.end()..]);
+        } else if let Some(quoted) = trimmed.strip_prefix('>') {
+            blocks.flush_paragraph();
+            blocks.flush_list();
+            let quoted = blocks.inline(quoted.trim_start());
+            blocks.quote.push(quoted);
+            blocks.formatted = true;
+        } else {
+            blocks.flush_quote();
+            blocks.flush_list();
+            let line = blocks.inline(line.trim_end());
+            blocks.paragraph.push(line);
+        }
+    }
+    if let Some((lang, lines)) = code {
+        blocks.html.push(code_block(&lang, &lines.join("\n")));
+    }
+    blocks.flush();
+
+    if !blocks.formatted {
+        return None;
+    }
+    let html = match blocks.html.as_slice() {
+        [only] if only.starts_with("<p>") => {
+            only["<p>".len()..only.len() - "</p>".len()].to_string()
+        }
+        all => all.concat(),
+    };
+    Some(html)
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    line[level..]
+        .strip_prefix(' ')
+        .map(|text| (level, text.trim()))
+}
+
+fn code_block(lang: &str, code: &str) -> String {
+    let class = lang
+        .split_whitespace()
+        .next()
+        .map(|l| format!(" class=\"language-{}\"", escape_html(l)))
+        .unwrap_or_default();
+    format!("<pre><code{}>{}</code></pre>", class, escape_html(code))
 }
 
 /// Real Matrix Client-Server API HTTP client using reqwest.
@@ -161,6 +811,9 @@ pub struct RealMatrixHttp {
     client: reqwest::Client,
     homeserver_url: String,
     access_token: String,
+    /// Transaction ids must be unique per access token, including across
+    /// restarts, or the homeserver deduplicates the message away.
+    txn_prefix: String,
     txn_counter: std::sync::atomic::AtomicU64,
 }
 
@@ -170,29 +823,55 @@ impl RealMatrixHttp {
             client: reqwest::Client::new(),
             homeserver_url: homeserver_url.trim_end_matches('/').to_string(),
             access_token,
+            txn_prefix: format!("rustant{}", chrono::Utc::now().timestamp_millis()),
             txn_counter: std::sync::atomic::AtomicU64::new(0),
         }
     }
+
+    async fn get(&self, url: &str) -> Result<(reqwest::StatusCode, serde_json::Value), String> {
+        let resp = self
+            .client
+            .get(url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .send()
+            .await
+            .map_err(|e| format!("HTTP error: {e}"))?;
+        let status = resp.status();
+        let body: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {e}"))?;
+        Ok((status, body))
+    }
+}
+
+fn api_error(status: reqwest::StatusCode, body: &serde_json::Value) -> String {
+    let err = body["error"].as_str().unwrap_or("unknown error");
+    format!("Matrix API error ({}): {}", status, err)
 }
 
 #[async_trait]
 impl MatrixHttpClient for RealMatrixHttp {
-    async fn send_message(&self, room_id: &str, text: &str) -> Result<String, String> {
+    async fn send_message(
+        &self,
+        room_id: &str,
+        content: serde_json::Value,
+    ) -> Result<String, String> {
         let txn_id = self
             .txn_counter
             .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
         let url = format!(
-            "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
-            self.homeserver_url, room_id, txn_id
+            "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}-{}",
+            self.homeserver_url,
+            urlencoding::encode(room_id),
+            self.txn_prefix,
+            txn_id
         );
         let resp = self
             .client
             .put(&url)
             .header("Authorization", format!("Bearer {}", self.access_token))
-            .json(&serde_json::json!({
-                "msgtype": "m.text",
-                "body": text,
-            }))
+            .json(&content)
             .send()
             .await
             .map_err(|e| format!("HTTP error: {e}"))?;
@@ -204,74 +883,34 @@ impl MatrixHttpClient for RealMatrixHttp {
             .map_err(|e| format!("JSON parse error: {e}"))?;
 
         if !status.is_success() {
-            let err = body["error"].as_str().unwrap_or("unknown error");
-            return Err(format!("Matrix API error ({}): {}", status, err));
+            return Err(api_error(status, &body));
         }
 
         let event_id = body["event_id"].as_str().unwrap_or("").to_string();
         Ok(event_id)
     }
 
-    async fn sync(&self, since: Option<&str>) -> Result<Vec<MatrixEvent>, String> {
-        let mut url = format!(
-            "{}/_matrix/client/v3/sync?timeout=30000",
-            self.homeserver_url
-        );
-        if let Some(since) = since {
-            url.push_str(&format!("&since={}", since));
-        }
-        let resp = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.access_token))
-            .send()
-            .await
-            .map_err(|e| format!("HTTP error: {e}"))?;
-
-        let body: serde_json::Value = resp
-            .json()
-            .await
-            .map_err(|e| format!("JSON parse error: {e}"))?;
-
-        let mut events = Vec::new();
-        if let Some(rooms) = body["rooms"]["join"].as_object() {
-            for (room_id, room_data) in rooms {
-                if let Some(timeline) = room_data["timeline"]["events"].as_array() {
-                    for event in timeline {
-                        if event["type"].as_str() == Some("m.room.message")
-                            && let Some(event_body) = event["content"]["body"].as_str()
-                        {
-                            events.push(MatrixEvent {
-                                event_id: event["event_id"].as_str().unwrap_or("").to_string(),
-                                room_id: room_id.clone(),
-                                sender: event["sender"].as_str().unwrap_or("").to_string(),
-                                body: event_body.to_string(),
-                            });
-                        }
-                    }
-                }
-            }
+    async fn sync(&self, since: Option<&str>) -> Result<MatrixSync, String> {
+        // The initial sync only fetches a position, so it returns at once.
+        let url = match since {
+            Some(since) => format!(
+                "{}/_matrix/client/v3/sync?timeout=30000&since={}",
+                self.homeserver_url,
+                urlencoding::encode(since)
+            ),
+            None => format!("{}/_matrix/client/v3/sync?timeout=0", self.homeserver_url),
+        };
+        let (status, body) = self.get(&url).await?;
+        if !status.is_success() {
+            return Err(api_error(status, &body));
         }
-
-        Ok(events)
+        Ok(parse_sync(&body))
     }
 
     async fn login(&self) -> Result<String, String> {
         // When using an access token, verify it with whoami
         let url = format!("{}/_matrix/client/v3/account/whoami", self.homeserver_url);
-        let resp = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.access_token))
-            .send()
-            .await
-            .map_err(|e| format!("HTTP error: {e}"))?;
-
-        let status = resp.status();
-        let body: serde_json::Value = resp
-            .json()
-            .await
-            .map_err(|e| format!("JSON parse error: {e}"))?;
+        let (status, body) = self.get(&url).await?;
 
         if !status.is_success() {
             let err = body["error"].as_str().unwrap_or("unauthorized");
@@ -281,59 +920,163 @@ impl MatrixHttpClient for RealMatrixHttp {
         let user_id = body["user_id"].as_str().unwrap_or("").to_string();
         Ok(user_id)
     }
+
+    async fn resolve_alias(&self, alias: &str) -> Result<String, String> {
+        let url = format!(
+            "{}/_matrix/client/v3/directory/room/{}",
+            self.homeserver_url,
+            urlencoding::encode(alias)
+        );
+        let (status, body) = self.get(&url).await?;
+        if !status.is_success() {
+            return Err(api_error(status, &body));
+        }
+        body["room_id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "response has no room_id".to_string())
+    }
+
+    async fn is_encrypted(&self, room_id: &str) -> Result<bool, String> {
+        let url = format!(
+            "{}/_matrix/client/v3/rooms/{}/state/m.room.encryption",
+            self.homeserver_url,
+            urlencoding::encode(room_id)
+        );
+        let (status, body) = self.get(&url).await?;
+        match status {
+            s if s.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            s => Err(api_error(s, &body)),
+        }
+    }
 }
 
 /// Create a Matrix channel with a real HTTP client.
 pub fn create_matrix_channel(config: MatrixConfig) -> MatrixChannel {
-    let http = RealMatrixHttp::new(config.homeserver_url.clone(), config.access_token.clone());
+    let resolved_token = config.resolve_access_token().unwrap_or_else(|e| {
+        tracing::warn!(
+            "Failed to resolve Matrix access token: {}. Falling back to raw value.",
+            e
+        );
+        config.access_token.as_str().to_string()
+    });
+    let http = RealMatrixHttp::new(config.homeserver_url.clone(), resolved_token);
     MatrixChannel::new(config, Box::new(http))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Arc;
 
-    struct MockMatrixHttp;
+    #[derive(Default)]
+    struct MockMatrixHttp {
+        sent: Arc<Mutex<Vec<(String, serde_json::Value)>>>,
+        syncs: Arc<Mutex<Vec<Option<String>>>>,
+        encrypted_rooms: Vec<String>,
+    }
 
     #[async_trait]
     impl MatrixHttpClient for MockMatrixHttp {
-        async fn send_message(&self, _room_id: &str, _text: &str) -> Result<String, String> {
+        async fn send_message(
+            &self,
+            room_id: &str,
+            content: serde_json::Value,
+        ) -> Result<String, String> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((room_id.to_string(), content));
             Ok("$event1".to_string())
         }
-        async fn sync(&self, _since: Option<&str>) -> Result<Vec<MatrixEvent>, String> {
-            Ok(vec![MatrixEvent {
-                event_id: "$ev1".into(),
-                room_id: "!room1:example.com".into(),
-                sender: "@alice:example.com".into(),
-                body: "hello matrix".into(),
-            }])
+        async fn sync(&self, since: Option<&str>) -> Result<MatrixSync, String> {
+            let mut syncs = self.syncs.lock().unwrap();
+            syncs.push(since.map(str::to_string));
+            Ok(MatrixSync {
+                next_batch: format!("s{}", syncs.len()),
+                events: vec![
+                    MatrixEvent {
+                        event_id: "$ev1".into(),
+                        room_id: "!room1:example.com".into(),
+                        sender: "@alice:example.com".into(),
+                        body: "hello matrix".into(),
+                        ..Default::default()
+                    },
+                    MatrixEvent {
+                        event_id: "$ev2".into(),
+                        room_id: "!room1:example.com".into(),
+                        sender: "@alice:example.com".into(),
+                        body: "in a thread".into(),
+                        thread_root: Some("$root".into()),
+                        in_reply_to: Some("$ev1".into()),
+                        ..Default::default()
+                    },
+                    MatrixEvent {
+                        event_id: "$ev3".into(),
+                        room_id: "!room1:example.com".into(),
+                        sender: "@bot:example.com".into(),
+                        body: "my own reply".into(),
+                        ..Default::default()
+                    },
+                    MatrixEvent {
+                        event_id: "$ev4".into(),
+                        room_id: "!secret:example.com".into(),
+                        sender: "@alice:example.com".into(),
+                        encrypted: true,
+                        ..Default::default()
+                    },
+                ],
+                encrypted_rooms: Vec::new(),
+            })
         }
         async fn login(&self) -> Result<String, String> {
-            Ok("token123".to_string())
+            Ok("@bot:example.com".to_string())
+        }
+        async fn resolve_alias(&self, alias: &str) -> Result<String, String> {
+            match alias {
+                "#ops:example.com" => Ok("!room1:example.com".to_string()),
+                _ => Err("M_NOT_FOUND".to_string()),
+            }
+        }
+        async fn is_encrypted(&self, room_id: &str) -> Result<bool, String> {
+            Ok(self.encrypted_rooms.iter().any(|r| r == room_id))
+        }
+    }
+
+    fn test_config(dir: &tempfile::TempDir) -> MatrixConfig {
+        MatrixConfig {
+            homeserver_url: "https://matrix.example.com".into(),
+            access_token: SecretRef::inline("token"),
+            sync_state_path: Some(dir.path().join("matrix.json")),
+            ..Default::default()
         }
     }
 
     #[tokio::test]
     async fn test_matrix_connect() {
+        let dir = tempfile::tempdir().unwrap();
         let config = MatrixConfig {
-            homeserver_url: "https://matrix.example.com".into(),
-            access_token: "token".into(),
             user_id: "@bot:example.com".into(),
-            room_ids: vec![],
+            ..test_config(&dir)
         };
-        let mut ch = MatrixChannel::new(config, Box::new(MockMatrixHttp));
+        let mut ch = MatrixChannel::new(config, Box::new(MockMatrixHttp::default()));
         ch.connect().await.unwrap();
         assert!(ch.is_connected());
     }
 
+    #[tokio::test]
+    async fn test_matrix_connect_requires_token() {
+        let mut ch =
+            MatrixChannel::new(MatrixConfig::default(), Box::new(MockMatrixHttp::default()));
+        assert!(ch.connect().await.is_err());
+        assert!(!ch.is_connected());
+    }
+
     #[tokio::test]
     async fn test_matrix_send() {
-        let config = MatrixConfig {
-            homeserver_url: "https://matrix.example.com".into(),
-            access_token: "token".into(),
-            ..Default::default()
-        };
-        let mut ch = MatrixChannel::new(config, Box::new(MockMatrixHttp));
+        let dir = tempfile::tempdir().unwrap();
+        let mut ch = MatrixChannel::new(test_config(&dir), Box::new(MockMatrixHttp::default()));
         ch.connect().await.unwrap();
 
         let sender = ChannelUser::new("@bot:ex.com", ChannelType::Matrix);
@@ -342,24 +1085,203 @@ mod tests {
         assert_eq!(id.0, "$event1");
     }
 
+    #[tokio::test]
+    async fn test_matrix_send_resolves_alias_and_formats_thread_reply() {
+        let dir = tempfile::tempdir().unwrap();
+        let http = MockMatrixHttp::default();
+        let sent = http.sent.clone();
+        let mut ch = MatrixChannel::new(test_config(&dir), Box::new(http));
+        ch.connect().await.unwrap();
+
+        let sender = ChannelUser::new("@bot:example.com", ChannelType::Matrix);
+        let msg = ChannelMessage::text(
+            ChannelType::Matrix,
+            "#ops:example.com",
+            sender,
+            "Build is **green**",
+        )
+        .with_thread(ThreadId::new("$root"));
+        ch.send_message(msg).await.unwrap();
+
+        let sent = sent.lock().unwrap();
+        let (room, content) = &sent[0];
+        assert_eq!(room, "!room1:example.com");
+        assert_eq!(content["body"], "Build is **green**");
+        assert_eq!(content["format"], "org.matrix.custom.html");
+        assert_eq!(content["formatted_body"], "Build is <strong>green</strong>");
+        let relates = &content["m.relates_to"];
+        assert_eq!(relates["rel_type"], "m.thread");
+        assert_eq!(relates["event_id"], "$root");
+        assert_eq!(relates["is_falling_back"], true);
+        assert_eq!(relates["m.in_reply_to"]["event_id"], "$root");
+    }
+
     #[tokio::test]
     async fn test_matrix_receive() {
-        let config = MatrixConfig {
-            homeserver_url: "https://matrix.example.com".into(),
-            access_token: "token".into(),
-            ..Default::default()
-        };
-        let mut ch = MatrixChannel::new(config, Box::new(MockMatrixHttp));
+        let dir = tempfile::tempdir().unwrap();
+        let mut ch = MatrixChannel::new(test_config(&dir), Box::new(MockMatrixHttp::default()));
         ch.connect().await.unwrap();
 
+        // The initial sync only establishes the position.
+        assert!(ch.receive_messages().await.unwrap().is_empty());
+
         let msgs = ch.receive_messages().await.unwrap();
-        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs.len(), 2);
         assert_eq!(msgs[0].content.as_text(), Some("hello matrix"));
+        assert_eq!(msgs[0].id, MessageId::new("$ev1"));
+        assert_eq!(msgs[1].thread_id, Some(ThreadId::new("$root")));
+        assert_eq!(msgs[1].reply_to, Some(MessageId::new("$ev1")));
+        assert_eq!(
+            ch.encrypted_rooms(),
+            vec!["!secret:example.com".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_matrix_sync_token_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut first = MatrixChannel::new(test_config(&dir), Box::new(MockMatrixHttp::default()));
+        first.connect().await.unwrap();
+        first.receive_messages().await.unwrap();
+        assert_eq!(first.sync_token().as_deref(), Some("s1"));
+
+        let http = MockMatrixHttp::default();
+        let syncs = http.syncs.clone();
+        let mut second = MatrixChannel::new(test_config(&dir), Box::new(http));
+        second.connect().await.unwrap();
+        let (msgs, cursor) = second.receive_messages_since(None).await.unwrap();
+        assert_eq!(syncs.lock().unwrap()[0].as_deref(), Some("s1"));
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(cursor.as_deref(), Some("s1"));
+    }
+
+    #[tokio::test]
+    async fn test_matrix_receive_since_cursor() {
+        let dir = tempfile::tempdir().unwrap();
+        let http = MockMatrixHttp::default();
+        let syncs = http.syncs.clone();
+        let mut ch = MatrixChannel::new(test_config(&dir), Box::new(http));
+        ch.connect().await.unwrap();
+
+        let (msgs, cursor) = ch.receive_messages_since(Some("s41")).await.unwrap();
+        assert_eq!(syncs.lock().unwrap()[0].as_deref(), Some("s41"));
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(cursor.as_deref(), Some("s1"));
+    }
+
+    #[tokio::test]
+    async fn test_matrix_encrypted_rooms_not_supported() {
+        let dir = tempfile::tempdir().unwrap();
+        let http = MockMatrixHttp {
+            encrypted_rooms: vec!["!room1:example.com".into()],
+            ..Default::default()
+        };
+        let sent = http.sent.clone();
+        let mut ch = MatrixChannel::new(test_config(&dir), Box::new(http));
+        ch.connect().await.unwrap();
+
+        let sender = ChannelUser::new("@bot:example.com", ChannelType::Matrix);
+        let msg = ChannelMessage::text(ChannelType::Matrix, "#ops:example.com", sender, "hi");
+        let err = ch.send_message(msg).await.unwrap_err();
+        assert!(err.to_string().contains("encryption not yet supported"));
+        assert!(sent.lock().unwrap().is_empty());
+        assert_eq!(ch.encrypted_rooms(), vec!["!room1:example.com".to_string()]);
+
+        // Listening only in encrypted rooms fails the connection outright.
+        let config = MatrixConfig {
+            room_ids: vec!["#ops:example.com".into()],
+            ..test_config(&dir)
+        };
+        let http = MockMatrixHttp {
+            encrypted_rooms: vec!["!room1:example.com".into()],
+            ..Default::default()
+        };
+        let mut ch = MatrixChannel::new(config, Box::new(http));
+        assert!(ch.connect().await.is_err());
+        assert_eq!(ch.status(), ChannelStatus::Failed);
+    }
+
+    #[test]
+    fn test_parse_sync() {
+        let body = json!({
+            "next_batch": "s72595_4483_1934",
+            "rooms": { "join": { "!room1:example.com": {
+                "state": { "events": [] },
+                "timeline": { "events": [
+                    { "type": "m.room.message", "event_id": "$a", "sender": "@alice:example.com",
+                      "content": { "msgtype": "m.text", "body": "deploy?" } },
+                    { "type": "m.room.message", "event_id": "$b", "sender": "@bob:example.com",
+                      "content": { "msgtype": "m.text", "body": "> <@alice:example.com> deploy?\n\nyes",
+                                   "m.relates_to": { "m.in_reply_to": { "event_id": "$a" } } } },
+                    { "type": "m.room.message", "event_id": "$c", "sender": "@bob:example.com",
+                      "content": { "msgtype": "m.text", "body": "* yes!",
+                                   "m.relates_to": { "rel_type": "m.replace", "event_id": "$b" } } },
+                    { "type": "m.room.message", "event_id": "$d", "sender": "@carol:example.com",
+                      "content": { "msgtype": "m.text", "body": "thread note",
+                                   "m.relates_to": { "rel_type": "m.thread", "event_id": "$a",
+                                                     "is_falling_back": true,
+                                                     "m.in_reply_to": { "event_id": "$a" } } } }
+                ] }
+            },
+            "!secret:example.com": {
+                "state": { "events": [
+                    { "type": "m.room.encryption", "state_key": "",
+                      "content": { "algorithm": "m.megolm.v1.aes-sha2" } }
+                ] },
+                "timeline": { "events": [
+                    { "type": "m.room.encrypted", "event_id": "$e", "sender": "@alice:example.com",
+                      "content": { "algorithm": "m.megolm.v1.aes-sha2" } }
+                ] }
+            } } }
+        });
+        let sync = parse_sync(&body);
+        assert_eq!(sync.next_batch, "s72595_4483_1934");
+        assert_eq!(
+            sync.encrypted_rooms,
+            vec!["!secret:example.com".to_string()]
+        );
+
+        let ids: Vec<&str> = sync.events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["$a", "$b", "$d", "$e"]);
+        assert_eq!(sync.events[1].body, "yes");
+        assert_eq!(sync.events[1].in_reply_to.as_deref(), Some("$a"));
+        assert_eq!(sync.events[2].thread_root.as_deref(), Some("$a"));
+        assert_eq!(sync.events[2].in_reply_to, None);
+        assert!(sync.events[3].encrypted);
+    }
+
+    #[test]
+    fn test_markdown_to_html() {
+        assert_eq!(markdown_to_html("just text"), None);
+        assert_eq!(markdown_to_html("a < b & snake_case_name"), None);
+        assert_eq!(
+            markdown_to_html("Run `cargo test` *now*, see [docs](https://docs.rs)"),
+            Some(
+                "Run <code>cargo test</code> <em>now</em>, see <a href=\"https://docs.rs\">docs</a>"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            markdown_to_html("## Status\n\n- build **ok**\n- tests ~~red~~\n\n1. fix\n2. ship"),
+            Some(
+                "<h2>Status</h2><ul><li>build <strong>ok</strong></li><li>tests <del>red</del></li></ul>\
+                 <ol><li>fix</li><li>ship</li></ol>"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            markdown_to_html("> quoted\n\n```rust\nlet x = a < b;\n```"),
+            Some(
+                "<blockquote>quoted</blockquote>\
+                 <pre><code class=\"language-rust\">let x = a &lt; b;</code></pre>"
+                    .to_string()
+            )
+        );
     }
 
     #[test]
     fn test_matrix_capabilities() {
-        let ch = MatrixChannel::new(MatrixConfig::default(), Box::new(MockMatrixHttp));
+        let ch = MatrixChannel::new(MatrixConfig::default(), Box::new(MockMatrixHttp::default()));
         let caps = ch.capabilities();
         assert!(caps.supports_threads);
         assert!(caps.supports_reactions);
@@ -371,7 +1293,7 @@ mod tests {
 
     #[test]
     fn test_matrix_streaming_mode() {
-        let ch = MatrixChannel::new(MatrixConfig::default(), Box::new(MockMatrixHttp));
+        let ch = MatrixChannel::new(MatrixConfig::default(), Box::new(MockMatrixHttp::default()));
         assert_eq!(ch.streaming_mode(), StreamingMode::LongPolling);
     }
 }
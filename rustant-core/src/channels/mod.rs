@@ -10,6 +10,7 @@ pub mod digest;
 pub mod discord;
 pub mod email;
 pub mod email_intelligence;
+pub mod email_mime;
 pub mod imessage;
 pub mod intelligence;
 pub mod irc;
@@ -23,6 +24,7 @@ pub mod slack;
 pub mod sms;
 pub mod teams;
 pub mod telegram;
+pub mod thread_context;
 pub mod types;
 pub mod webchat;
 pub mod webhook;
@@ -33,7 +35,10 @@ pub mod style_tracker;
 
 pub use agent_bridge::ChannelAgentBridge;
 pub use auto_reply::{AutoReplyEngine, PendingReply, ReplyStatus};
-pub use digest::{ChannelDigest, DigestActionItem, DigestCollector, DigestHighlight};
+pub use digest::{
+    ChannelDigest, DigestActionItem, DigestCollector, DigestDelivery, DigestFollowUp,
+    DigestHighlight, DigestRunner, DigestScheduler,
+};
 pub use email_intelligence::{
     EmailCategory, EmailClassification, EmailIntelligence, SenderProfile,
 };
@@ -43,19 +48,25 @@ pub use intelligence::{
     MessageClassifier, MessageType, SuggestedAction,
 };
 pub use irc::{IrcChannel, IrcConfig};
-pub use manager::{ChannelManager, build_channel_manager};
+pub use manager::{ChannelManager, OutgoingMessage, build_channel_manager};
 pub use normalize::MessageNormalizer;
-pub use routing::{ChannelRouter, RoutingCondition, RoutingRule};
+pub use routing::{
+    ChannelRouter, RoutingCondition, RoutingConfig, RoutingError, RoutingInput, RoutingRule,
+    RoutingTrace, RuleOutcome, RuleTrace,
+};
 pub use scheduler_bridge::{FollowUpReminder, ReminderStatus, SchedulerBridge};
 pub use sms::{SmsChannel, SmsConfig};
 pub use teams::{TeamsChannel, TeamsConfig};
+pub use thread_context::{ThreadContext, ThreadContextConfig};
 pub use types::{
-    ChannelCapabilities, ChannelMessage, ChannelStatus, ChannelType, ChannelUser, MessageContent,
-    MessageId, StreamingMode, ThreadId,
+    ChannelCapabilities, ChannelMessage, ChannelStatus, ChannelType, ChannelUser, DeliveryStatus,
+    MessageContent, MessageId, StreamingMode, ThreadId,
 };
 pub use webhook::{WebhookChannel, WebhookConfig};
 
-pub use cdc::{CdcAction, CdcConfig, CdcProcessor, CdcState};
+pub use cdc::{
+    CdcAction, CdcConfig, CdcFacts, CdcProcessor, CdcSourceConfig, CdcState, SourceSyncState,
+};
 pub use style_tracker::{CommunicationStyleTracker, SenderStyleProfile};
 
 use crate::error::RustantError;
@@ -109,6 +120,29 @@ pub trait Channel: Send + Sync {
         let msgs = self.receive_messages().await?;
         Ok((msgs, None))
     }
+
+    /// Messages in a thread, oldest first, for reply context. When `after`
+    /// is set only messages newer than it are returned. At most `limit`
+    /// messages, the most recent ones. Default: none.
+    async fn thread_history(
+        &self,
+        _channel_id: &str,
+        _thread_id: &ThreadId,
+        _after: Option<&MessageId>,
+        _limit: usize,
+    ) -> Result<Vec<ChannelMessage>, RustantError> {
+        Ok(Vec::new())
+    }
+
+    /// The most recent messages in a conversation, oldest first, for reply
+    /// context on channels without threads. Default: none.
+    async fn recent_messages(
+        &self,
+        _channel_id: &str,
+        _limit: usize,
+    ) -> Result<Vec<ChannelMessage>, RustantError> {
+        Ok(Vec::new())
+    }
 }
 
 #[cfg(test)]
@@ -1,11 +1,21 @@
 //! Channel routing — rule-based routing of incoming messages to agents.
+//!
+//! Rules are evaluated in ascending `priority` order. A matching rule applies
+//! its actions (target agent, message priority) and stops evaluation unless
+//! it is marked `fallthrough`, which lets broad rules such as "everything
+//! from #incidents is Urgent" combine with later, more specific targets.
+//! [`ChannelRouter::trace`] reports every rule's outcome for dry runs
+//! (`rustant channel route-test`).
 
+use super::intelligence::MessageType;
 use super::{ChannelMessage, ChannelType};
+use crate::config::MessagePriority;
+use chrono::NaiveTime;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 /// A routing condition used to match messages.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum RoutingCondition {
     /// Match by channel type.
     ChannelType(ChannelType),
@@ -15,21 +25,175 @@ pub enum RoutingCondition {
     MessageContains(String),
     /// Match by command prefix (e.g., "/agent2").
     CommandPrefix(String),
+    /// Match if the text content matches a regular expression.
+    ContentRegex(String),
+    /// Match if the sender's ID or display name is in the list.
+    SenderIn(Vec<String>),
+    /// Match the channel ID or name (`channel_name` metadata) against a
+    /// pattern where `*` matches any run of characters (e.g. "#incident*").
+    ChannelName(String),
+    /// Match if the local time falls in `[start, end)` ("HH:MM"); windows
+    /// may wrap midnight ("22:00" to "06:00").
+    TimeWindow { start: String, end: String },
+    /// Match the type assigned by the `MessageClassifier`.
+    Category(MessageType),
+    /// Match if every nested condition matches.
+    All(Vec<RoutingCondition>),
+    /// Match if at least one nested condition matches.
+    Any(Vec<RoutingCondition>),
 }
 
-/// A routing rule: conditions + target agent.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A routing rule: conditions (all must match) and the actions to apply.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RoutingRule {
+    /// Name shown in traces and validation errors.
+    #[serde(default)]
+    pub name: String,
+    /// Evaluation order (lower first). Must be unique within a router.
     pub priority: u32,
+    #[serde(default)]
     pub conditions: Vec<RoutingCondition>,
-    pub target_agent: Uuid,
+    /// Agent to route to. Optional for `fallthrough` rules that only set priority.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Priority to assign to matching messages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_priority: Option<MessagePriority>,
+    /// Keep evaluating later rules after this one matches.
+    #[serde(default)]
+    pub fallthrough: bool,
+}
+
+impl RoutingRule {
+    /// A terminal rule routing matching messages to `target`.
+    pub fn new(
+        priority: u32,
+        conditions: Vec<RoutingCondition>,
+        target: impl Into<String>,
+    ) -> Self {
+        Self {
+            priority,
+            conditions,
+            target: Some(target.into()),
+            ..Default::default()
+        }
+    }
+
+    fn label(&self) -> String {
+        if self.name.is_empty() {
+            format!("#{}", self.priority)
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+/// Routing rules as configured under `[channels.routing]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    /// Agent for messages no terminal rule claims.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_agent: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<RoutingRule>,
+}
+
+/// Problems that make a rule set ambiguous or partly dead.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RoutingError {
+    #[error(
+        "rules '{first}' and '{second}' share priority {priority}; evaluation order must be explicit"
+    )]
+    DuplicatePriority {
+        priority: u32,
+        first: String,
+        second: String,
+    },
+    #[error(
+        "rule '{rule}' can never match: '{shadowed_by}' is evaluated first and matches everything it does"
+    )]
+    Unreachable { rule: String, shadowed_by: String },
+    #[error("rule '{rule}' has an invalid regex '{pattern}': {message}")]
+    InvalidRegex {
+        rule: String,
+        pattern: String,
+        message: String,
+    },
+    #[error("rule '{rule}' has an invalid time '{value}' (expected HH:MM)")]
+    InvalidTime { rule: String, value: String },
+    #[error("rule '{rule}' has no target and is not fallthrough, so it routes nowhere")]
+    NoTarget { rule: String },
+}
+
+/// Everything about a message the conditions can look at.
+#[derive(Debug, Clone)]
+pub struct RoutingInput<'a> {
+    pub message: &'a ChannelMessage,
+    /// Classifier output, when available; `Category` conditions fail without it.
+    pub category: Option<&'a MessageType>,
+    /// Local time of day for `TimeWindow` conditions.
+    pub time: NaiveTime,
+}
+
+impl<'a> RoutingInput<'a> {
+    /// Input for `message` at the current local time, without a category.
+    pub fn new(message: &'a ChannelMessage) -> Self {
+        Self {
+            message,
+            category: None,
+            time: chrono::Local::now().time(),
+        }
+    }
+}
+
+/// How one rule fared during evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleOutcome {
+    /// All conditions matched; the rule's actions were applied.
+    Matched,
+    /// The first condition that failed.
+    NotMatched { failed: String },
+    /// Not evaluated because an earlier terminal rule matched.
+    Skipped,
+}
+
+/// One rule's entry in a [`RoutingTrace`].
+#[derive(Debug, Clone)]
+pub struct RuleTrace {
+    pub rule: String,
+    pub priority: u32,
+    pub outcome: RuleOutcome,
+}
+
+/// The full result of routing a message.
+#[derive(Debug, Clone, Default)]
+pub struct RoutingTrace {
+    /// Per-rule outcomes in evaluation order.
+    pub rules: Vec<RuleTrace>,
+    /// Final target agent (`None` if nothing matched and there is no default).
+    pub target: Option<String>,
+    /// Whether the target came from the default agent.
+    pub used_default: bool,
+    /// Priority assigned by matching rules, if any.
+    pub priority: Option<MessagePriority>,
+}
+
+impl RoutingTrace {
+    /// Names of the rules that matched, in order.
+    pub fn matched_rules(&self) -> Vec<&str> {
+        self.rules
+            .iter()
+            .filter(|r| r.outcome == RuleOutcome::Matched)
+            .map(|r| r.rule.as_str())
+            .collect()
+    }
 }
 
 /// Routes incoming channel messages to the appropriate agent.
 #[derive(Debug, Clone, Default)]
 pub struct ChannelRouter {
     rules: Vec<RoutingRule>,
-    default_agent: Option<Uuid>,
+    default_agent: Option<String>,
 }
 
 impl ChannelRouter {
@@ -37,16 +201,31 @@ impl ChannelRouter {
         Self::default()
     }
 
+    /// Build a router from configuration, rejecting invalid rule sets.
+    pub fn from_config(config: &RoutingConfig) -> Result<Self, Vec<RoutingError>> {
+        let mut router = Self::new();
+        router.default_agent = config.default_agent.clone();
+        for rule in &config.rules {
+            router.add_rule(rule.clone());
+        }
+        let errors = router.validate();
+        if errors.is_empty() {
+            Ok(router)
+        } else {
+            Err(errors)
+        }
+    }
+
     /// Set the default agent for unmatched messages.
-    pub fn with_default_agent(mut self, agent_id: Uuid) -> Self {
-        self.default_agent = Some(agent_id);
+    pub fn with_default_agent(mut self, agent: impl Into<String>) -> Self {
+        self.default_agent = Some(agent.into());
         self
     }
 
     /// Add a routing rule.
     pub fn add_rule(&mut self, rule: RoutingRule) {
         self.rules.push(rule);
-        self.rules.sort_by(|a, b| a.priority.cmp(&b.priority));
+        self.rules.sort_by_key(|a| a.priority);
     }
 
     /// Number of rules configured.
@@ -54,38 +233,231 @@ impl ChannelRouter {
         self.rules.len()
     }
 
-    /// Route a message to the appropriate agent. Returns the target agent ID.
-    pub fn route(&self, msg: &ChannelMessage) -> Option<Uuid> {
+    /// Rules in evaluation order.
+    pub fn rules(&self) -> &[RoutingRule] {
+        &self.rules
+    }
+
+    /// Check the rule set for duplicate priorities, rules shadowed by a
+    /// broader earlier rule, invalid patterns, and rules without a target.
+    pub fn validate(&self) -> Vec<RoutingError> {
+        let mut errors = Vec::new();
+        for pair in self.rules.windows(2) {
+            if pair[0].priority == pair[1].priority {
+                errors.push(RoutingError::DuplicatePriority {
+                    priority: pair[0].priority,
+                    first: pair[0].label(),
+                    second: pair[1].label(),
+                });
+            }
+        }
+        for (i, rule) in self.rules.iter().enumerate() {
+            if rule.target.is_none() && !rule.fallthrough {
+                errors.push(RoutingError::NoTarget { rule: rule.label() });
+            }
+            for cond in &rule.conditions {
+                validate_condition(&rule.label(), cond, &mut errors);
+            }
+            // A terminal rule whose conditions are a subset of a later rule's
+            // matches every message the later rule would.
+            if let Some(earlier) = self.rules[..i].iter().find(|earlier| {
+                !earlier.fallthrough
+                    && earlier.priority != rule.priority
+                    && earlier
+                        .conditions
+                        .iter()
+                        .all(|c| rule.conditions.contains(c))
+            }) {
+                errors.push(RoutingError::Unreachable {
+                    rule: rule.label(),
+                    shadowed_by: earlier.label(),
+                });
+            }
+        }
+        errors
+    }
+
+    /// Route a message to the appropriate agent. Returns the target agent.
+    pub fn route(&self, msg: &ChannelMessage) -> Option<String> {
+        self.trace(&RoutingInput::new(msg)).target
+    }
+
+    /// Evaluate the rules against `input`, recording every rule's outcome.
+    /// Routing has no side effects, so this doubles as a dry run.
+    pub fn trace(&self, input: &RoutingInput<'_>) -> RoutingTrace {
+        let mut trace = RoutingTrace::default();
+        let mut done = false;
         for rule in &self.rules {
-            if self.matches_rule(rule, msg) {
-                return Some(rule.target_agent);
+            let outcome = if done {
+                RuleOutcome::Skipped
+            } else {
+                match rule
+                    .conditions
+                    .iter()
+                    .find(|cond| !matches_condition(cond, input))
+                {
+                    Some(failed) => RuleOutcome::NotMatched {
+                        failed: describe_condition(failed),
+                    },
+                    None => {
+                        if let Some(target) = &rule.target {
+                            trace.target = Some(target.clone());
+                        }
+                        if rule.set_priority.is_some() {
+                            trace.priority = rule.set_priority;
+                        }
+                        done = !rule.fallthrough;
+                        RuleOutcome::Matched
+                    }
+                }
+            };
+            trace.rules.push(RuleTrace {
+                rule: rule.label(),
+                priority: rule.priority,
+                outcome,
+            });
+        }
+        if trace.target.is_none() {
+            trace.target = self.default_agent.clone();
+            trace.used_default = trace.target.is_some();
+        }
+        trace
+    }
+}
+
+fn validate_condition(rule: &str, cond: &RoutingCondition, errors: &mut Vec<RoutingError>) {
+    match cond {
+        RoutingCondition::ContentRegex(pattern) => {
+            if let Err(e) = regex::Regex::new(pattern) {
+                errors.push(RoutingError::InvalidRegex {
+                    rule: rule.to_string(),
+                    pattern: pattern.clone(),
+                    message: e.to_string(),
+                });
+            }
+        }
+        RoutingCondition::TimeWindow { start, end } => {
+            for value in [start, end] {
+                if parse_time(value).is_none() {
+                    errors.push(RoutingError::InvalidTime {
+                        rule: rule.to_string(),
+                        value: value.clone(),
+                    });
+                }
             }
         }
-        self.default_agent
+        RoutingCondition::All(nested) | RoutingCondition::Any(nested) => {
+            for cond in nested {
+                validate_condition(rule, cond, errors);
+            }
+        }
+        _ => {}
     }
+}
 
-    fn matches_rule(&self, rule: &RoutingRule, msg: &ChannelMessage) -> bool {
-        rule.conditions
-            .iter()
-            .all(|cond| self.matches_condition(cond, msg))
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+fn matches_condition(cond: &RoutingCondition, input: &RoutingInput<'_>) -> bool {
+    let msg = input.message;
+    let text = msg.content.as_text();
+    match cond {
+        RoutingCondition::ChannelType(ct) => msg.channel_type == *ct,
+        RoutingCondition::UserId(id) => msg.sender.id == *id,
+        RoutingCondition::MessageContains(sub) => {
+            text.map(|t| t.contains(sub.as_str())).unwrap_or(false)
+        }
+        RoutingCondition::CommandPrefix(prefix) => text
+            .map(|t| t.starts_with(prefix.as_str()))
+            .unwrap_or(false),
+        RoutingCondition::ContentRegex(pattern) => match (regex::Regex::new(pattern), text) {
+            (Ok(re), Some(t)) => re.is_match(t),
+            _ => false,
+        },
+        RoutingCondition::SenderIn(senders) => senders.iter().any(|s| {
+            *s == msg.sender.id
+                || msg
+                    .sender
+                    .display_name
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(s))
+        }),
+        RoutingCondition::ChannelName(pattern) => {
+            glob_match(pattern, &msg.channel_id)
+                || msg
+                    .metadata
+                    .get("channel_name")
+                    .is_some_and(|name| glob_match(pattern, name))
+        }
+        RoutingCondition::TimeWindow { start, end } => match (parse_time(start), parse_time(end)) {
+            (Some(start), Some(end)) if start <= end => input.time >= start && input.time < end,
+            (Some(start), Some(end)) => input.time >= start || input.time < end,
+            _ => false,
+        },
+        RoutingCondition::Category(category) => input.category == Some(category),
+        RoutingCondition::All(nested) => nested.iter().all(|c| matches_condition(c, input)),
+        RoutingCondition::Any(nested) => nested.iter().any(|c| matches_condition(c, input)),
     }
+}
 
-    fn matches_condition(&self, cond: &RoutingCondition, msg: &ChannelMessage) -> bool {
-        match cond {
-            RoutingCondition::ChannelType(ct) => msg.channel_type == *ct,
-            RoutingCondition::UserId(id) => msg.sender.id == *id,
-            RoutingCondition::MessageContains(sub) => msg
-                .content
-                .as_text()
-                .map(|t| t.contains(sub.as_str()))
-                .unwrap_or(false),
-            RoutingCondition::CommandPrefix(prefix) => msg
-                .content
-                .as_text()
-                .map(|t| t.starts_with(prefix.as_str()))
-                .unwrap_or(false),
+/// Case-insensitive match where `*` matches any run of characters. A
+/// leading `#` is optional on both sides, since platforms differ on it.
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let pattern = pattern.trim_start_matches('#').to_lowercase();
+    let value = value.trim_start_matches('#').to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+    let mut rest = value.as_str();
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            match rest.strip_prefix(part) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        } else {
+            match rest.find(part) {
+                Some(pos) => rest = &rest[pos + part.len()..],
+                None => return false,
+            }
         }
     }
+    true
+}
+
+/// Short human-readable form of a condition for traces.
+fn describe_condition(cond: &RoutingCondition) -> String {
+    match cond {
+        RoutingCondition::ChannelType(ct) => format!("channel is {}", ct),
+        RoutingCondition::UserId(id) => format!("sender is {}", id),
+        RoutingCondition::MessageContains(sub) => format!("text contains '{}'", sub),
+        RoutingCondition::CommandPrefix(prefix) => format!("text starts with '{}'", prefix),
+        RoutingCondition::ContentRegex(pattern) => format!("text matches /{}/", pattern),
+        RoutingCondition::SenderIn(senders) => format!("sender in [{}]", senders.join(", ")),
+        RoutingCondition::ChannelName(pattern) => format!("channel name matches '{}'", pattern),
+        RoutingCondition::TimeWindow { start, end } => format!("time in {}-{}", start, end),
+        RoutingCondition::Category(category) => format!("category is {:?}", category),
+        RoutingCondition::All(nested) => format!(
+            "all of ({})",
+            nested
+                .iter()
+                .map(describe_condition)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        RoutingCondition::Any(nested) => format!(
+            "any of ({})",
+            nested
+                .iter()
+                .map(describe_condition)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
 }
 
 #[cfg(test)]
@@ -98,6 +470,14 @@ mod tests {
         ChannelMessage::text(channel_type, "ch1", sender, text)
     }
 
+    fn at(msg: &ChannelMessage, hh: u32, mm: u32) -> RoutingInput<'_> {
+        RoutingInput {
+            message: msg,
+            category: None,
+            time: NaiveTime::from_hms_opt(hh, mm, 0).unwrap(),
+        }
+    }
+
     #[test]
     fn test_router_no_rules_no_default() {
         let router = ChannelRouter::new();
@@ -107,52 +487,240 @@ mod tests {
 
     #[test]
     fn test_router_default_agent() {
-        let default_id = Uuid::new_v4();
-        let router = ChannelRouter::new().with_default_agent(default_id);
+        let router = ChannelRouter::new().with_default_agent("default");
         let msg = make_msg(ChannelType::Slack, "u1", "hello");
-        assert_eq!(router.route(&msg), Some(default_id));
+        assert_eq!(router.route(&msg).as_deref(), Some("default"));
     }
 
     #[test]
     fn test_router_channel_type_rule() {
-        let agent_tg = Uuid::new_v4();
-        let agent_sl = Uuid::new_v4();
-
         let mut router = ChannelRouter::new();
-        router.add_rule(RoutingRule {
-            priority: 1,
-            conditions: vec![RoutingCondition::ChannelType(ChannelType::Telegram)],
-            target_agent: agent_tg,
-        });
-        router.add_rule(RoutingRule {
-            priority: 2,
-            conditions: vec![RoutingCondition::ChannelType(ChannelType::Slack)],
-            target_agent: agent_sl,
-        });
+        router.add_rule(RoutingRule::new(
+            1,
+            vec![RoutingCondition::ChannelType(ChannelType::Telegram)],
+            "telegram-agent",
+        ));
+        router.add_rule(RoutingRule::new(
+            2,
+            vec![RoutingCondition::ChannelType(ChannelType::Slack)],
+            "slack-agent",
+        ));
 
         let tg_msg = make_msg(ChannelType::Telegram, "u1", "hi");
-        assert_eq!(router.route(&tg_msg), Some(agent_tg));
+        assert_eq!(router.route(&tg_msg).as_deref(), Some("telegram-agent"));
 
         let sl_msg = make_msg(ChannelType::Slack, "u1", "hi");
-        assert_eq!(router.route(&sl_msg), Some(agent_sl));
+        assert_eq!(router.route(&sl_msg).as_deref(), Some("slack-agent"));
     }
 
     #[test]
     fn test_router_command_prefix_rule() {
-        let special_agent = Uuid::new_v4();
-        let default_agent = Uuid::new_v4();
+        let mut router = ChannelRouter::new().with_default_agent("default");
+        router.add_rule(RoutingRule::new(
+            1,
+            vec![RoutingCondition::CommandPrefix("/admin".into())],
+            "admin",
+        ));
+
+        let admin_msg = make_msg(ChannelType::Telegram, "u1", "/admin status");
+        assert_eq!(router.route(&admin_msg).as_deref(), Some("admin"));
+
+        let normal_msg = make_msg(ChannelType::Telegram, "u1", "hello");
+        assert_eq!(router.route(&normal_msg).as_deref(), Some("default"));
+    }
 
-        let mut router = ChannelRouter::new().with_default_agent(default_agent);
+    fn incident_config() -> RoutingConfig {
+        toml::from_str(
+            r##"
+            default_agent = "default"
+
+            [[rules]]
+            name = "sev1-incidents"
+            priority = 10
+            target = "ops"
+            set_priority = "critical"
+            conditions = [
+                { channel_name = "#incidents" },
+                { content_regex = "(?i)\\bSEV ?1\\b" },
+            ]
+
+            [[rules]]
+            name = "slack"
+            priority = 20
+            target = "default"
+            conditions = [{ channel_type = "Slack" }]
+            "##,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_incident_routing_from_config() {
+        let router = ChannelRouter::from_config(&incident_config()).unwrap();
+
+        let mut sev1 = make_msg(ChannelType::Slack, "alice", "SEV1 db down");
+        sev1.channel_id = "C123".into();
+        sev1.metadata
+            .insert("channel_name".into(), "incidents".into());
+        let trace = router.trace(&RoutingInput::new(&sev1));
+        assert_eq!(trace.target.as_deref(), Some("ops"));
+        assert_eq!(trace.priority, Some(MessagePriority::Urgent));
+        assert_eq!(trace.matched_rules(), vec!["sev1-incidents"]);
+        assert_eq!(trace.rules[1].outcome, RuleOutcome::Skipped);
+
+        let chatter = make_msg(ChannelType::Slack, "alice", "lunch?");
+        let trace = router.trace(&RoutingInput::new(&chatter));
+        assert_eq!(trace.target.as_deref(), Some("default"));
+        assert!(!trace.used_default);
+        assert_eq!(
+            trace.rules[0].outcome,
+            RuleOutcome::NotMatched {
+                failed: "channel name matches '#incidents'".into()
+            }
+        );
+        assert_eq!(trace.priority, None);
+    }
+
+    #[test]
+    fn test_any_all_groups_sender_and_category() {
+        let mut router = ChannelRouter::new().with_default_agent("default");
+        router.add_rule(RoutingRule::new(
+            1,
+            vec![RoutingCondition::Any(vec![
+                RoutingCondition::SenderIn(vec!["alice".into(), "Bob".into()]),
+                RoutingCondition::All(vec![
+                    RoutingCondition::ChannelType(ChannelType::Email),
+                    RoutingCondition::Category(MessageType::ActionRequired),
+                ]),
+            ])],
+            "triage",
+        ));
+
+        let alice = make_msg(ChannelType::Slack, "alice", "hi");
+        assert_eq!(router.route(&alice).as_deref(), Some("triage"));
+
+        let mut bob = make_msg(ChannelType::Slack, "U42", "hi");
+        bob.sender.display_name = Some("bob".into());
+        assert_eq!(router.route(&bob).as_deref(), Some("triage"));
+
+        let email = make_msg(ChannelType::Email, "carol", "please sign");
+        assert_eq!(router.route(&email).as_deref(), Some("default"));
+        let input = RoutingInput {
+            category: Some(&MessageType::ActionRequired),
+            ..RoutingInput::new(&email)
+        };
+        assert_eq!(router.trace(&input).target.as_deref(), Some("triage"));
+    }
+
+    #[test]
+    fn test_fallthrough_chain_and_time_window() {
+        let mut router = ChannelRouter::new().with_default_agent("default");
         router.add_rule(RoutingRule {
+            name: "after-hours".into(),
             priority: 1,
-            conditions: vec![RoutingCondition::CommandPrefix("/admin".into())],
-            target_agent: special_agent,
+            conditions: vec![RoutingCondition::TimeWindow {
+                start: "22:00".into(),
+                end: "06:00".into(),
+            }],
+            set_priority: Some(MessagePriority::Low),
+            fallthrough: true,
+            ..Default::default()
         });
+        router.add_rule(RoutingRule::new(
+            2,
+            vec![RoutingCondition::MessageContains("deploy".into())],
+            "ops",
+        ));
 
-        let admin_msg = make_msg(ChannelType::Telegram, "u1", "/admin status");
-        assert_eq!(router.route(&admin_msg), Some(special_agent));
+        let msg = make_msg(ChannelType::Slack, "u1", "deploy finished");
+        let night = router.trace(&at(&msg, 23, 30));
+        assert_eq!(night.matched_rules(), vec!["after-hours", "#2"]);
+        assert_eq!(night.target.as_deref(), Some("ops"));
+        assert_eq!(night.priority, Some(MessagePriority::Low));
 
-        let normal_msg = make_msg(ChannelType::Telegram, "u1", "hello");
-        assert_eq!(router.route(&normal_msg), Some(default_agent));
+        let day = router.trace(&at(&msg, 12, 0));
+        assert_eq!(day.matched_rules(), vec!["#2"]);
+        assert_eq!(day.priority, None);
+
+        let other = make_msg(ChannelType::Slack, "u1", "hello");
+        let trace = router.trace(&at(&other, 5, 59));
+        assert!(trace.used_default);
+        assert_eq!(trace.priority, Some(MessagePriority::Low));
+    }
+
+    #[test]
+    fn test_validation_rejects_shadowed_and_ambiguous_rules() {
+        let config = RoutingConfig {
+            default_agent: None,
+            rules: vec![
+                RoutingRule {
+                    name: "all-slack".into(),
+                    ..RoutingRule::new(
+                        1,
+                        vec![RoutingCondition::ChannelType(ChannelType::Slack)],
+                        "default",
+                    )
+                },
+                RoutingRule {
+                    name: "slack-sev1".into(),
+                    ..RoutingRule::new(
+                        2,
+                        vec![
+                            RoutingCondition::ChannelType(ChannelType::Slack),
+                            RoutingCondition::MessageContains("SEV1".into()),
+                        ],
+                        "ops",
+                    )
+                },
+                RoutingRule::new(3, vec![RoutingCondition::ContentRegex("(".into())], "x"),
+                RoutingRule::new(3, vec![RoutingCondition::UserId("u".into())], "y"),
+                RoutingRule {
+                    priority: 4,
+                    conditions: vec![RoutingCondition::TimeWindow {
+                        start: "25:00".into(),
+                        end: "06:00".into(),
+                    }],
+                    ..Default::default()
+                },
+            ],
+        };
+        let errors = ChannelRouter::from_config(&config).unwrap_err();
+        assert!(errors.contains(&RoutingError::Unreachable {
+            rule: "slack-sev1".into(),
+            shadowed_by: "all-slack".into()
+        }));
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, RoutingError::DuplicatePriority { priority: 3, .. }))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, RoutingError::InvalidRegex { .. }))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, RoutingError::InvalidTime { value, .. } if value == "25:00"))
+        );
+        assert!(errors.contains(&RoutingError::NoTarget { rule: "#4".into() }));
+
+        // Fallthrough rules don't shadow anything.
+        let mut router = ChannelRouter::new();
+        router.add_rule(RoutingRule {
+            fallthrough: true,
+            ..config.rules[0].clone()
+        });
+        router.add_rule(config.rules[1].clone());
+        assert!(router.validate().is_empty());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("#incident*", "incidents"));
+        assert!(glob_match("*-alerts", "#prod-alerts"));
+        assert!(glob_match("ops-*-oncall", "ops-eu-oncall"));
+        assert!(!glob_match("#incidents", "incidents-archive"));
     }
 }
@@ -1,39 +1,386 @@
-//! Signal channel via signal-cli subprocess bridge.
+//! Signal channel via signal-cli's JSON-RPC interface.
 //!
-//! Communicates with the Signal network through the signal-cli tool
-//! using a subprocess bridge. In tests, a trait abstraction mocks the CLI.
+//! The channel either spawns `signal-cli jsonRpc` and talks to it over
+//! stdio, or connects to the socket of an already running
+//! `signal-cli daemon --socket`. Incoming envelopes arrive as `receive`
+//! notifications and are queued until the next poll. Group chats map to
+//! stable `group:<id>` channel and thread ids, attachments are saved under
+//! `attachments_dir`, and delivery/read receipts update the status of sent
+//! messages. If the signal-cli process dies, the bridge reconnects with
+//! backoff on the next call.
+//!
+//! Sends are spaced and capped per minute to stay within Signal's sending
+//! limits; a rate-limit error from the server pauses sending for a while.
+//! In tests, a trait abstraction mocks the CLI.
 
+use super::email::sanitize_component;
 use super::{
     Channel, ChannelCapabilities, ChannelMessage, ChannelStatus, ChannelType, ChannelUser,
-    MessageId, StreamingMode,
+    DeliveryStatus, MessageContent, MessageId, StreamingMode, ThreadId,
 };
 use crate::error::{ChannelError, RustantError};
 use async_trait::async_trait;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::oneshot;
+
+/// Oldest signal-cli release the JSON-RPC bridge is tested against.
+pub const MIN_SIGNAL_CLI_VERSION: (u32, u32, u32) = (0, 13, 0);
+/// How long a JSON-RPC call may take before it is abandoned.
+const RPC_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longest wait between reconnection attempts to signal-cli.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+/// Sending pauses this long after Signal reports a rate limit.
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(300);
+/// Incoming events buffered between polls; the oldest are dropped beyond it.
+const MAX_QUEUED_EVENTS: usize = 1000;
+/// Sent messages whose receipts are tracked.
+const MAX_TRACKED_SENDS: usize = 1000;
 
 /// Configuration for a Signal channel.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignalConfig {
     pub phone_number: String,
+    /// signal-cli executable, spawned in JSON-RPC mode unless `socket_path`
+    /// is set. Also used by `rustant channel setup signal` to link.
+    #[serde(default = "default_signal_cli_path")]
     pub signal_cli_path: String,
+    /// Socket of a running `signal-cli daemon --socket` to connect to
+    /// instead of spawning signal-cli.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket_path: Option<PathBuf>,
+    #[serde(default)]
     pub allowed_contacts: Vec<String>,
+    /// Directory where incoming attachments are saved.
+    #[serde(default = "default_attachments_dir")]
+    pub attachments_dir: PathBuf,
+    /// Minimum spacing between two sends.
+    #[serde(default = "default_min_send_interval_ms")]
+    pub min_send_interval_ms: u64,
+    /// Sends allowed in any 60-second window.
+    #[serde(default = "default_max_sends_per_minute")]
+    pub max_sends_per_minute: usize,
 }
 
-/// Trait for signal-cli interactions.
-#[async_trait]
-pub trait SignalCliBridge: Send + Sync {
-    async fn send_message(&self, recipient: &str, text: &str) -> Result<(), String>;
-    async fn receive_messages(&self) -> Result<Vec<SignalIncoming>, String>;
-    async fn is_registered(&self) -> Result<bool, String>;
+fn default_signal_cli_path() -> String {
+    "signal-cli".to_string()
+}
+
+fn default_attachments_dir() -> PathBuf {
+    PathBuf::from(".rustant/inbox/signal")
+}
+
+fn default_min_send_interval_ms() -> u64 {
+    1000
+}
+
+fn default_max_sends_per_minute() -> usize {
+    20
+}
+
+impl Default for SignalConfig {
+    fn default() -> Self {
+        Self {
+            phone_number: String::new(),
+            signal_cli_path: default_signal_cli_path(),
+            socket_path: None,
+            allowed_contacts: Vec::new(),
+            attachments_dir: default_attachments_dir(),
+            min_send_interval_ms: default_min_send_interval_ms(),
+            max_sends_per_minute: default_max_sends_per_minute(),
+        }
+    }
+}
+
+/// Where a Signal message goes: a contact or a group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignalTarget {
+    Contact(String),
+    Group(String),
+}
+
+impl SignalTarget {
+    /// Parse a channel or thread id; group ids carry a `group:` prefix.
+    pub fn parse(id: &str) -> Self {
+        match id.strip_prefix("group:") {
+            Some(group) => Self::Group(group.to_string()),
+            None => Self::Contact(id.to_string()),
+        }
+    }
+
+    /// The stable channel/thread id for this target.
+    pub fn id(&self) -> String {
+        match self {
+            Self::Contact(number) => number.clone(),
+            Self::Group(group) => format!("group:{group}"),
+        }
+    }
+
+    fn rpc_params(&self) -> Value {
+        match self {
+            Self::Contact(number) => json!({ "recipient": [number] }),
+            Self::Group(group) => json!({ "groupId": group }),
+        }
+    }
+}
+
+/// An outgoing Signal message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalOutgoing {
+    pub target: SignalTarget,
+    pub text: String,
+    /// Local files to attach.
+    pub attachments: Vec<PathBuf>,
+}
+
+/// An attachment on an incoming message, not yet downloaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalAttachment {
+    pub id: String,
+    pub content_type: String,
+    pub filename: Option<String>,
+    pub size: Option<u64>,
 }
 
 /// An incoming Signal message.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SignalIncoming {
     pub sender: String,
     pub sender_name: Option<String>,
     pub text: String,
+    /// Milliseconds since the epoch; Signal's message identifier.
     pub timestamp: u64,
+    pub group_id: Option<String>,
+    pub attachments: Vec<SignalAttachment>,
+}
+
+/// A delivery or read receipt for messages we sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalReceipt {
+    pub sender: String,
+    pub status: DeliveryStatus,
+    /// Timestamps of the messages the receipt covers.
+    pub timestamps: Vec<u64>,
+}
+
+/// An event received from signal-cli.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignalEvent {
+    Message(SignalIncoming),
+    Receipt(SignalReceipt),
+}
+
+/// Trait for signal-cli interactions.
+#[async_trait]
+pub trait SignalCliBridge: Send + Sync {
+    /// signal-cli's version, e.g. `0.13.4`.
+    async fn version(&self) -> Result<String, String>;
+    async fn is_registered(&self) -> Result<bool, String>;
+    /// Send a message, returning its Signal timestamp.
+    async fn send(&self, message: &SignalOutgoing) -> Result<u64, String>;
+    /// Events received since the last call.
+    async fn receive(&self) -> Result<Vec<SignalEvent>, String>;
+    /// Download an attachment of a message received from `source`.
+    async fn fetch_attachment(&self, id: &str, source: &SignalTarget) -> Result<Vec<u8>, String>;
+    /// Known groups as `(id, name)` pairs.
+    async fn list_groups(&self) -> Result<Vec<(String, String)>, String>;
+    /// Release the connection to signal-cli.
+    async fn close(&self) {}
+    /// Whether the connection to signal-cli is currently up.
+    fn is_alive(&self) -> bool {
+        true
+    }
+}
+
+/// Parse a signal-cli version string such as `signal-cli 0.13.4-SNAPSHOT`.
+pub fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let version = version.trim();
+    let version = version.rsplit(' ').next().unwrap_or(version);
+    let numeric = version.split(['-', '+']).next()?;
+    let mut parts = numeric.split('.').map(|p| p.parse::<u32>().ok());
+    let major = parts.next()??;
+    let minor = parts.next()??;
+    let patch = parts.next().flatten().unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+/// Refuse signal-cli releases older than [`MIN_SIGNAL_CLI_VERSION`].
+pub fn check_version(version: &str) -> Result<(), String> {
+    let (major, minor, patch) = MIN_SIGNAL_CLI_VERSION;
+    match parse_version(version) {
+        Some(found) if found >= MIN_SIGNAL_CLI_VERSION => Ok(()),
+        Some(_) => Err(format!(
+            "signal-cli {} is older than the minimum supported {major}.{minor}.{patch}",
+            version.trim()
+        )),
+        None => Err(format!(
+            "Could not parse signal-cli version '{}'",
+            version.trim()
+        )),
+    }
+}
+
+/// Run `signal-cli --version` and return its output.
+pub async fn signal_cli_version(cli_path: &str) -> Result<String, String> {
+    let output = tokio::process::Command::new(cli_path)
+        .arg("--version")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run {cli_path}: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{cli_path} --version failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether a signal-cli error reports a server-side rate limit or challenge.
+fn is_rate_limit_error(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    [
+        "rate limit",
+        "ratelimit",
+        "proofrequired",
+        "status code: 429",
+    ]
+    .iter()
+    .any(|needle| lower.contains(needle))
+}
+
+/// Parse a `receive` notification's envelope into an event.
+pub fn parse_envelope(envelope: &Value) -> Option<SignalEvent> {
+    let sender = ["sourceNumber", "source", "sourceUuid"]
+        .iter()
+        .find_map(|key| envelope[*key].as_str().filter(|s| !s.is_empty()))?
+        .to_string();
+
+    if let Some(receipt) = envelope["receiptMessage"].as_object() {
+        let flag = |key: &str| receipt.get(key).and_then(Value::as_bool) == Some(true);
+        let status = if flag("isRead") || flag("isViewed") {
+            DeliveryStatus::Read
+        } else if flag("isDelivery") {
+            DeliveryStatus::Delivered
+        } else {
+            return None;
+        };
+        let timestamps = receipt
+            .get("timestamps")
+            .and_then(Value::as_array)
+            .map(|ts| ts.iter().filter_map(Value::as_u64).collect())
+            .unwrap_or_default();
+        return Some(SignalEvent::Receipt(SignalReceipt {
+            sender,
+            status,
+            timestamps,
+        }));
+    }
+
+    let data = envelope["dataMessage"].as_object()?;
+    let attachments: Vec<SignalAttachment> = data
+        .get("attachments")
+        .and_then(Value::as_array)
+        .map(|list| {
+            list.iter()
+                .filter_map(|a| {
+                    Some(SignalAttachment {
+                        id: a["id"].as_str()?.to_string(),
+                        content_type: a["contentType"]
+                            .as_str()
+                            .unwrap_or("application/octet-stream")
+                            .to_string(),
+                        filename: a["filename"].as_str().map(str::to_string),
+                        size: a["size"].as_u64(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let text = data
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    if text.is_empty() && attachments.is_empty() {
+        // Typing indicators, reactions, profile key updates and the like.
+        return None;
+    }
+    Some(SignalEvent::Message(SignalIncoming {
+        sender,
+        sender_name: envelope["sourceName"]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .map(str::to_string),
+        text,
+        timestamp: data
+            .get("timestamp")
+            .and_then(Value::as_u64)
+            .or_else(|| envelope["timestamp"].as_u64())
+            .unwrap_or(0),
+        group_id: data
+            .get("groupInfo")
+            .and_then(|g| g["groupId"].as_str())
+            .map(str::to_string),
+        attachments,
+    }))
+}
+
+/// Spaces sends and caps them per minute, with a cooldown after the server
+/// reports a rate limit.
+#[derive(Debug)]
+struct SendThrottle {
+    min_interval: Duration,
+    per_minute: usize,
+    /// Scheduled times of recent sends, oldest first.
+    recent: VecDeque<Instant>,
+    cooldown_until: Option<Instant>,
+}
+
+impl SendThrottle {
+    fn new(config: &SignalConfig) -> Self {
+        Self {
+            min_interval: Duration::from_millis(config.min_send_interval_ms),
+            per_minute: config.max_sends_per_minute.max(1),
+            recent: VecDeque::new(),
+            cooldown_until: None,
+        }
+    }
+
+    /// Reserve a send slot, returning how long to wait before sending, or
+    /// `None` while cooling down or once the per-minute cap is reached.
+    fn reserve(&mut self, now: Instant) -> Option<Duration> {
+        if self.cooldown_until.is_some_and(|until| now < until) {
+            return None;
+        }
+        while self
+            .recent
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) >= Duration::from_secs(60))
+        {
+            self.recent.pop_front();
+        }
+        if self.recent.len() >= self.per_minute {
+            return None;
+        }
+        let at = self
+            .recent
+            .back()
+            .map_or(now, |last| (*last + self.min_interval).max(now));
+        self.recent.push_back(at);
+        Some(at - now)
+    }
+
+    fn cool_down(&mut self, now: Instant) {
+        self.cooldown_until = Some(now + RATE_LIMIT_COOLDOWN);
+    }
 }
 
 /// Signal channel.
@@ -42,15 +389,23 @@ pub struct SignalChannel {
     status: ChannelStatus,
     bridge: Box<dyn SignalCliBridge>,
     name: String,
+    throttle: Mutex<SendThrottle>,
+    /// Delivery status of sent messages, keyed by Signal timestamp.
+    sent: Mutex<BTreeMap<u64, DeliveryStatus>>,
+    group_names: Mutex<HashMap<String, String>>,
 }
 
 impl SignalChannel {
     pub fn new(config: SignalConfig, bridge: Box<dyn SignalCliBridge>) -> Self {
+        let throttle = Mutex::new(SendThrottle::new(&config));
         Self {
             config,
             status: ChannelStatus::Disconnected,
             bridge,
             name: "signal".to_string(),
+            throttle,
+            sent: Mutex::new(BTreeMap::new()),
+            group_names: Mutex::new(HashMap::new()),
         }
     }
 
@@ -58,6 +413,206 @@ impl SignalChannel {
         self.name = name.into();
         self
     }
+
+    /// Delivery status of a message sent through this channel, if tracked.
+    pub fn delivery_status(&self, id: &MessageId) -> Option<DeliveryStatus> {
+        let timestamp = id.0.parse::<u64>().ok()?;
+        self.sent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&timestamp)
+            .copied()
+    }
+
+    fn send_error(&self, message: impl Into<String>) -> RustantError {
+        RustantError::Channel(ChannelError::SendFailed {
+            name: self.name.clone(),
+            message: message.into(),
+        })
+    }
+
+    fn rate_limited(&self) -> RustantError {
+        RustantError::Channel(ChannelError::RateLimited {
+            name: self.name.clone(),
+        })
+    }
+
+    /// Turn an outgoing channel message into a Signal send. Attachments must
+    /// be local files.
+    fn to_outgoing(&self, msg: &ChannelMessage) -> Result<SignalOutgoing, RustantError> {
+        let target = match &msg.thread_id {
+            Some(thread) if thread.0.starts_with("group:") => SignalTarget::parse(&thread.0),
+            _ => SignalTarget::parse(&msg.channel_id),
+        };
+        let (text, file) = match &msg.content {
+            MessageContent::Text { text } => (text.clone(), None),
+            MessageContent::File { url, .. } => (String::new(), Some(url)),
+            MessageContent::Image { url, alt_text } => {
+                (alt_text.clone().unwrap_or_default(), Some(url))
+            }
+            MessageContent::Media { url, caption, .. } => {
+                (caption.clone().unwrap_or_default(), Some(url))
+            }
+            _ => {
+                return Err(self.send_error(
+                    "only text, file, image and media messages can be sent over Signal",
+                ));
+            }
+        };
+        let attachments = match file {
+            Some(url) if url.contains("://") => {
+                return Err(self.send_error(format!("attachment '{url}' is not a local file")));
+            }
+            Some(path) => vec![PathBuf::from(path)],
+            None => Vec::new(),
+        };
+        Ok(SignalOutgoing {
+            target,
+            text,
+            attachments,
+        })
+    }
+
+    fn apply_receipt(&self, receipt: &SignalReceipt) {
+        let mut sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+        for timestamp in &receipt.timestamps {
+            if let Some(status) = sent.get_mut(timestamp) {
+                *status = (*status).max(receipt.status);
+            }
+        }
+    }
+
+    async fn group_name(&self, group_id: &str) -> Option<String> {
+        if let Some(name) = self
+            .group_names
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(group_id)
+        {
+            return Some(name.clone());
+        }
+        let groups = self
+            .bridge
+            .list_groups()
+            .await
+            .inspect_err(
+                |e| tracing::debug!(channel = %self.name, "Listing Signal groups failed: {}", e),
+            )
+            .ok()?;
+        let mut names = self.group_names.lock().unwrap_or_else(|e| e.into_inner());
+        names.extend(groups);
+        names.get(group_id).cloned()
+    }
+
+    async fn save_attachment(
+        &self,
+        message: &SignalIncoming,
+        source: &SignalTarget,
+        attachment: &SignalAttachment,
+    ) -> Result<PathBuf, String> {
+        let data = self.bridge.fetch_attachment(&attachment.id, source).await?;
+        let dir = self
+            .config
+            .attachments_dir
+            .join(sanitize_component(&source.id()))
+            .join(message.timestamp.to_string());
+        let filename = attachment.filename.as_deref().unwrap_or(&attachment.id);
+        let path = dir.join(sanitize_component(filename));
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| e.to_string())?;
+        tokio::fs::write(&path, &data)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(path)
+    }
+
+    async fn to_channel_messages(&self, message: SignalIncoming) -> Vec<ChannelMessage> {
+        let source = match &message.group_id {
+            Some(group) => SignalTarget::Group(group.clone()),
+            None => SignalTarget::Contact(message.sender.clone()),
+        };
+        let channel_id = source.id();
+        let id = MessageId::new(message.timestamp.to_string());
+        let timestamp = chrono::DateTime::from_timestamp_millis(message.timestamp as i64)
+            .unwrap_or_else(chrono::Utc::now);
+
+        let mut sender = ChannelUser::new(&message.sender, ChannelType::Signal);
+        if let Some(name) = &message.sender_name {
+            sender = sender.with_name(name);
+        }
+        let mut text =
+            ChannelMessage::text(ChannelType::Signal, &channel_id, sender, &message.text);
+        text.id = id.clone();
+        text.timestamp = timestamp;
+        if let SignalTarget::Group(group) = &source {
+            text = text.with_thread(ThreadId::new(&channel_id));
+            if let Some(name) = self.group_name(group).await {
+                text = text.with_metadata("group_name", name);
+            }
+        }
+
+        let mut files = Vec::new();
+        let mut skipped = Vec::new();
+        for attachment in &message.attachments {
+            let label = attachment
+                .filename
+                .clone()
+                .unwrap_or_else(|| attachment.id.clone());
+            match self.save_attachment(&message, &source, attachment).await {
+                Ok(path) => files.push((path, attachment, label)),
+                Err(e) => {
+                    tracing::warn!(
+                        channel = %self.name,
+                        attachment = %label,
+                        "Failed to save Signal attachment: {}", e
+                    );
+                    skipped.push(label);
+                }
+            }
+        }
+        if !files.is_empty() {
+            let paths: Vec<String> = files
+                .iter()
+                .map(|(p, ..)| p.display().to_string())
+                .collect();
+            text = text.with_metadata("attachments", paths.join("\n"));
+        }
+        if !skipped.is_empty() {
+            text = text.with_metadata("skipped_attachments", skipped.join("\n"));
+        }
+
+        let mut messages = vec![text.clone()];
+        for (path, attachment, filename) in files {
+            let url = path.display().to_string();
+            let mut file = text.clone().with_reply_to(id.clone());
+            file.id = MessageId::random();
+            file.metadata.remove("attachments");
+            file.metadata.remove("skipped_attachments");
+            file.content = if attachment.content_type.starts_with("image/") {
+                MessageContent::Image {
+                    url,
+                    alt_text: attachment.filename.clone(),
+                }
+            } else if attachment.content_type.starts_with("audio/")
+                || attachment.content_type.starts_with("video/")
+            {
+                MessageContent::Media {
+                    url,
+                    mime_type: attachment.content_type.clone(),
+                    caption: attachment.filename.clone(),
+                }
+            } else {
+                MessageContent::File {
+                    url,
+                    filename,
+                    size_bytes: attachment.size,
+                }
+            };
+            messages.push(file.with_metadata("content_type", &attachment.content_type));
+        }
+        messages
+    }
 }
 
 #[async_trait]
@@ -76,12 +631,19 @@ impl Channel for SignalChannel {
                 name: self.name.clone(),
             }));
         }
-        let registered = self.bridge.is_registered().await.map_err(|e| {
+        let connection_failed = |message: String| {
             RustantError::Channel(ChannelError::ConnectionFailed {
                 name: self.name.clone(),
-                message: e,
+                message,
             })
-        })?;
+        };
+        let version = self.bridge.version().await.map_err(connection_failed)?;
+        check_version(&version).map_err(connection_failed)?;
+        let registered = self
+            .bridge
+            .is_registered()
+            .await
+            .map_err(connection_failed)?;
         if !registered {
             return Err(RustantError::Channel(ChannelError::AuthFailed {
                 name: self.name.clone(),
@@ -92,52 +654,75 @@ impl Channel for SignalChannel {
     }
 
     async fn disconnect(&mut self) -> Result<(), RustantError> {
+        self.bridge.close().await;
         self.status = ChannelStatus::Disconnected;
         Ok(())
     }
 
     async fn send_message(&self, msg: ChannelMessage) -> Result<MessageId, RustantError> {
-        let text = msg.content.as_text().unwrap_or("");
-        self.bridge
-            .send_message(&msg.channel_id, text)
-            .await
-            .map_err(|e| {
-                RustantError::Channel(ChannelError::SendFailed {
-                    name: self.name.clone(),
-                    message: e,
-                })
-            })?;
-        Ok(MessageId::random())
+        let outgoing = self.to_outgoing(&msg)?;
+        let wait = self
+            .throttle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .reserve(Instant::now())
+            .ok_or_else(|| self.rate_limited())?;
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+
+        let timestamp = match self.bridge.send(&outgoing).await {
+            Ok(timestamp) => timestamp,
+            Err(e) if is_rate_limit_error(&e) => {
+                tracing::warn!(channel = %self.name, "Signal rate limit hit, pausing sends: {}", e);
+                self.throttle
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .cool_down(Instant::now());
+                return Err(self.rate_limited());
+            }
+            Err(e) => return Err(self.send_error(e)),
+        };
+
+        let mut sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+        sent.insert(timestamp, DeliveryStatus::Sent);
+        while sent.len() > MAX_TRACKED_SENDS {
+            sent.pop_first();
+        }
+        Ok(MessageId::new(timestamp.to_string()))
     }
 
     async fn receive_messages(&self) -> Result<Vec<ChannelMessage>, RustantError> {
-        let incoming = self.bridge.receive_messages().await.map_err(|e| {
+        let events = self.bridge.receive().await.map_err(|e| {
             RustantError::Channel(ChannelError::ConnectionFailed {
                 name: self.name.clone(),
                 message: e,
             })
         })?;
 
-        let messages = incoming
-            .into_iter()
-            .filter(|m| {
-                self.config.allowed_contacts.is_empty()
-                    || self.config.allowed_contacts.contains(&m.sender)
-            })
-            .map(|m| {
-                let mut user = ChannelUser::new(&m.sender, ChannelType::Signal);
-                if let Some(name) = m.sender_name {
-                    user = user.with_name(name);
+        let mut messages = Vec::new();
+        for event in events {
+            match event {
+                SignalEvent::Receipt(receipt) => self.apply_receipt(&receipt),
+                SignalEvent::Message(message) => {
+                    if !self.config.allowed_contacts.is_empty()
+                        && !self.config.allowed_contacts.contains(&message.sender)
+                    {
+                        continue;
+                    }
+                    messages.extend(self.to_channel_messages(message).await);
                 }
-                ChannelMessage::text(ChannelType::Signal, &m.sender, user, &m.text)
-            })
-            .collect();
-
+            }
+        }
         Ok(messages)
     }
 
     fn status(&self) -> ChannelStatus {
-        self.status
+        if self.status == ChannelStatus::Connected && !self.bridge.is_alive() {
+            ChannelStatus::Reconnecting
+        } else {
+            self.status
+        }
     }
 
     fn capabilities(&self) -> ChannelCapabilities {
@@ -154,92 +739,327 @@ impl Channel for SignalChannel {
     }
 
     fn streaming_mode(&self) -> StreamingMode {
-        StreamingMode::Polling { interval_ms: 5000 }
+        // Envelopes are pushed by signal-cli and queued; a poll only drains
+        // the local queue.
+        StreamingMode::Polling { interval_ms: 1000 }
     }
 }
 
-/// Real Signal CLI bridge using tokio subprocess.
-pub struct RealSignalCliBridge {
-    cli_path: String,
-    phone_number: String,
+type PendingCalls = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>>;
+
+/// One open JSON-RPC connection to signal-cli.
+struct RpcConnection {
+    writer: Box<dyn AsyncWrite + Send + Unpin>,
+    pending: PendingCalls,
+    alive: Arc<AtomicBool>,
+    reader: tokio::task::JoinHandle<()>,
+    /// The spawned signal-cli process, killed when the connection drops.
+    _child: Option<tokio::process::Child>,
 }
 
-impl RealSignalCliBridge {
-    pub fn new(cli_path: String, phone_number: String) -> Self {
-        Self {
-            cli_path,
-            phone_number,
-        }
+impl Drop for RpcConnection {
+    fn drop(&mut self) {
+        self.reader.abort();
     }
 }
 
-#[async_trait]
-impl SignalCliBridge for RealSignalCliBridge {
-    async fn send_message(&self, recipient: &str, text: &str) -> Result<(), String> {
-        let output = tokio::process::Command::new(&self.cli_path)
-            .args(["-u", &self.phone_number, "send", "-m", text, recipient])
-            .output()
-            .await
-            .map_err(|e| format!("Failed to run signal-cli: {e}"))?;
+#[derive(Debug, Default)]
+struct ReconnectState {
+    failures: u32,
+    next_attempt: Option<Instant>,
+}
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("signal-cli send failed: {}", stderr));
-        }
+/// signal-cli bridge over JSON-RPC, either to a spawned `signal-cli jsonRpc`
+/// process or to a running daemon's socket. signal-cli runs in
+/// multi-account mode, so every account-scoped call names the account.
+pub struct JsonRpcSignalBridge {
+    config: SignalConfig,
+    connection: tokio::sync::Mutex<Option<RpcConnection>>,
+    events: Arc<Mutex<VecDeque<SignalEvent>>>,
+    next_id: AtomicU64,
+    reconnect: Mutex<ReconnectState>,
+}
 
-        Ok(())
+impl JsonRpcSignalBridge {
+    pub fn new(config: SignalConfig) -> Self {
+        Self {
+            config,
+            connection: tokio::sync::Mutex::new(None),
+            events: Arc::new(Mutex::new(VecDeque::new())),
+            next_id: AtomicU64::new(1),
+            reconnect: Mutex::new(ReconnectState::default()),
+        }
     }
 
-    async fn receive_messages(&self) -> Result<Vec<SignalIncoming>, String> {
-        let output = tokio::process::Command::new(&self.cli_path)
-            .args([
-                "-u",
-                &self.phone_number,
-                "receive",
-                "--json",
-                "--timeout",
-                "5",
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("Failed to run signal-cli: {e}"))?;
+    /// Open a fresh connection, spawning signal-cli unless a socket is set.
+    async fn open(&self) -> Result<RpcConnection, String> {
+        let (reader, writer, child): (
+            Box<dyn AsyncRead + Send + Unpin>,
+            Box<dyn AsyncWrite + Send + Unpin>,
+            Option<tokio::process::Child>,
+        ) = match &self.config.socket_path {
+            #[cfg(unix)]
+            Some(socket) => {
+                let stream = tokio::net::UnixStream::connect(socket)
+                    .await
+                    .map_err(|e| format!("Failed to connect to {}: {e}", socket.display()))?;
+                let (reader, writer) = stream.into_split();
+                (Box::new(reader), Box::new(writer), None)
+            }
+            #[cfg(not(unix))]
+            Some(_) => {
+                return Err("signal-cli sockets are only supported on Unix".to_string());
+            }
+            None => {
+                let mut child = tokio::process::Command::new(&self.config.signal_cli_path)
+                    .arg("jsonRpc")
+                    .stdin(std::process::Stdio::piped())
+                    .stdout(std::process::Stdio::piped())
+                    .stderr(std::process::Stdio::null())
+                    .kill_on_drop(true)
+                    .spawn()
+                    .map_err(|e| format!("Failed to run signal-cli: {e}"))?;
+                let stdout = child.stdout.take().ok_or("signal-cli has no stdout")?;
+                let stdin = child.stdin.take().ok_or("signal-cli has no stdin")?;
+                (Box::new(stdout), Box::new(stdin), Some(child))
+            }
+        };
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut messages = Vec::new();
+        let pending: PendingCalls = Arc::new(Mutex::new(HashMap::new()));
+        let alive = Arc::new(AtomicBool::new(true));
+        let reader = tokio::spawn(read_loop(
+            BufReader::new(reader),
+            pending.clone(),
+            self.events.clone(),
+            alive.clone(),
+        ));
+        Ok(RpcConnection {
+            writer,
+            pending,
+            alive,
+            reader,
+            _child: child,
+        })
+    }
 
-        for line in stdout.lines() {
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(line)
-                && let Some(data_msg) = v["envelope"]["dataMessage"].as_object()
-                && let Some(text) = data_msg.get("message").and_then(|m| m.as_str())
+    /// Return the live connection, reconnecting with backoff if it died.
+    async fn connected<'a>(
+        &self,
+        slot: &'a mut Option<RpcConnection>,
+    ) -> Result<&'a mut RpcConnection, String> {
+        if slot
+            .as_ref()
+            .is_some_and(|c| c.alive.load(Ordering::SeqCst))
+        {
+            return Ok(slot.as_mut().expect("connection checked above"));
+        }
+        if slot.take().is_some() {
+            tracing::warn!("signal-cli connection lost, reconnecting");
+        }
+        {
+            let state = self.reconnect.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(at) = state.next_attempt
+                && let Some(wait) = at.checked_duration_since(Instant::now())
             {
-                messages.push(SignalIncoming {
-                    sender: v["envelope"]["source"].as_str().unwrap_or("").to_string(),
-                    sender_name: v["envelope"]["sourceName"].as_str().map(|s| s.to_string()),
-                    text: text.to_string(),
-                    timestamp: v["envelope"]["timestamp"].as_u64().unwrap_or(0),
-                });
+                return Err(format!(
+                    "signal-cli is unavailable; reconnecting in {}s",
+                    wait.as_secs().max(1)
+                ));
             }
         }
+        match self.open().await {
+            Ok(connection) => {
+                *self.reconnect.lock().unwrap_or_else(|e| e.into_inner()) =
+                    ReconnectState::default();
+                Ok(slot.insert(connection))
+            }
+            Err(e) => {
+                let mut state = self.reconnect.lock().unwrap_or_else(|e| e.into_inner());
+                state.failures += 1;
+                let delay =
+                    Duration::from_secs(1 << state.failures.min(6)).min(MAX_RECONNECT_DELAY);
+                state.next_attempt = Some(Instant::now() + delay);
+                Err(e)
+            }
+        }
+    }
 
-        Ok(messages)
+    async fn call(&self, method: &str, mut params: Value) -> Result<Value, String> {
+        if !matches!(method, "version" | "listAccounts") {
+            params["account"] = json!(self.config.phone_number);
+        }
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut slot = self.connection.lock().await;
+            let connection = self.connected(&mut slot).await?;
+            connection
+                .pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(id, tx);
+            let line = format!("{request}\n");
+            let written = async {
+                connection.writer.write_all(line.as_bytes()).await?;
+                connection.writer.flush().await
+            }
+            .await;
+            if let Err(e) = written {
+                connection.alive.store(false, Ordering::SeqCst);
+                return Err(format!("Failed to write to signal-cli: {e}"));
+            }
+        }
+        match tokio::time::timeout(RPC_TIMEOUT, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(format!("signal-cli exited before answering '{method}'")),
+            Err(_) => Err(format!(
+                "signal-cli did not answer '{method}' within {}s",
+                RPC_TIMEOUT.as_secs()
+            )),
+        }
+    }
+}
+
+/// Read JSON-RPC lines until the connection closes: answers resolve pending
+/// calls and `receive` notifications are queued as events.
+async fn read_loop(
+    reader: BufReader<Box<dyn AsyncRead + Send + Unpin>>,
+    pending: PendingCalls,
+    events: Arc<Mutex<VecDeque<SignalEvent>>>,
+    alive: Arc<AtomicBool>,
+) {
+    let mut lines = reader.lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let Ok(value) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if let Some(id) = value.get("id").and_then(Value::as_u64) {
+            let waiter = pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&id);
+            if let Some(waiter) = waiter {
+                let result = match value.get("error") {
+                    Some(error) => Err(error["message"]
+                        .as_str()
+                        .unwrap_or("unknown signal-cli error")
+                        .to_string()),
+                    None => Ok(value["result"].clone()),
+                };
+                let _ = waiter.send(result);
+            }
+        } else if value["method"] == "receive"
+            && let Some(event) = parse_envelope(&value["params"]["envelope"])
+        {
+            let mut queue = events.lock().unwrap_or_else(|e| e.into_inner());
+            if queue.len() >= MAX_QUEUED_EVENTS {
+                queue.pop_front();
+            }
+            queue.push_back(event);
+        }
+    }
+    alive.store(false, Ordering::SeqCst);
+    // Dropping the senders fails every call still waiting for an answer.
+    pending.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+#[async_trait]
+impl SignalCliBridge for JsonRpcSignalBridge {
+    async fn version(&self) -> Result<String, String> {
+        let result = self.call("version", json!({})).await?;
+        result["version"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "signal-cli did not report its version".to_string())
     }
 
     async fn is_registered(&self) -> Result<bool, String> {
-        let output = tokio::process::Command::new(&self.cli_path)
-            .args(["-u", &self.phone_number, "listAccounts"])
-            .output()
-            .await
-            .map_err(|e| format!("Failed to run signal-cli: {e}"))?;
+        let accounts = self.call("listAccounts", json!({})).await?;
+        Ok(accounts.as_array().is_some_and(|list| {
+            list.iter()
+                .any(|a| a["number"].as_str() == Some(self.config.phone_number.as_str()))
+        }))
+    }
+
+    async fn send(&self, message: &SignalOutgoing) -> Result<u64, String> {
+        let mut params = message.target.rpc_params();
+        params["message"] = json!(message.text);
+        if !message.attachments.is_empty() {
+            params["attachments"] = json!(
+                message
+                    .attachments
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+            );
+        }
+        let result = self.call("send", params).await?;
+        result["timestamp"]
+            .as_u64()
+            .ok_or_else(|| "signal-cli did not return a message timestamp".to_string())
+    }
+
+    async fn receive(&self) -> Result<Vec<SignalEvent>, String> {
+        // Make sure the connection (and with it, notification delivery) is up.
+        {
+            let mut slot = self.connection.lock().await;
+            self.connected(&mut slot).await?;
+        }
+        let mut queue = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(queue.drain(..).collect())
+    }
+
+    async fn fetch_attachment(&self, id: &str, source: &SignalTarget) -> Result<Vec<u8>, String> {
+        let params = match source {
+            SignalTarget::Contact(number) => json!({ "id": id, "recipient": number }),
+            SignalTarget::Group(group) => json!({ "id": id, "groupId": group }),
+        };
+        let result = self.call("getAttachment", params).await?;
+        let data = result["data"]
+            .as_str()
+            .or_else(|| result.as_str())
+            .ok_or_else(|| format!("signal-cli returned no data for attachment {id}"))?;
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| format!("Invalid attachment data: {e}"))
+    }
+
+    async fn list_groups(&self) -> Result<Vec<(String, String)>, String> {
+        let groups = self.call("listGroups", json!({})).await?;
+        Ok(groups
+            .as_array()
+            .map(|list| {
+                list.iter()
+                    .filter_map(|g| {
+                        Some((
+                            g["id"].as_str()?.to_string(),
+                            g["name"].as_str().unwrap_or_default().to_string(),
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        Ok(stdout.contains(&self.phone_number))
+    async fn close(&self) {
+        self.connection.lock().await.take();
+    }
+
+    fn is_alive(&self) -> bool {
+        // A held lock means a call is in flight on a live connection.
+        match self.connection.try_lock() {
+            Ok(slot) => slot
+                .as_ref()
+                .is_some_and(|c| c.alive.load(Ordering::SeqCst)),
+            Err(_) => true,
+        }
     }
 }
 
-/// Create a Signal channel with a real signal-cli bridge.
+/// Create a Signal channel with a JSON-RPC signal-cli bridge.
 pub fn create_signal_channel(config: SignalConfig) -> SignalChannel {
-    let bridge =
-        RealSignalCliBridge::new(config.signal_cli_path.clone(), config.phone_number.clone());
+    let bridge = JsonRpcSignalBridge::new(config.clone());
     SignalChannel::new(config, Box::new(bridge))
 }
 
@@ -247,79 +1067,308 @@ pub fn create_signal_channel(config: SignalConfig) -> SignalChannel {
 mod tests {
     use super::*;
 
+    #[derive(Default)]
     struct MockSignalBridge {
         registered: bool,
+        version: String,
+        events: Mutex<Vec<SignalEvent>>,
+        sent: Mutex<Vec<SignalOutgoing>>,
+        send_error: Option<String>,
     }
 
     impl MockSignalBridge {
         fn new(registered: bool) -> Self {
-            Self { registered }
+            Self {
+                registered,
+                version: "0.13.4".into(),
+                events: Mutex::new(vec![SignalEvent::Message(incoming("hello signal"))]),
+                ..Default::default()
+            }
         }
     }
 
     #[async_trait]
-    impl SignalCliBridge for MockSignalBridge {
-        async fn send_message(&self, _recipient: &str, _text: &str) -> Result<(), String> {
-            Ok(())
-        }
-        async fn receive_messages(&self) -> Result<Vec<SignalIncoming>, String> {
-            Ok(vec![SignalIncoming {
-                sender: "+1234567890".into(),
-                sender_name: Some("Alice".into()),
-                text: "hello signal".into(),
-                timestamp: 1000,
-            }])
+    impl SignalCliBridge for Arc<MockSignalBridge> {
+        async fn version(&self) -> Result<String, String> {
+            Ok(self.version.clone())
         }
         async fn is_registered(&self) -> Result<bool, String> {
             Ok(self.registered)
         }
+        async fn send(&self, message: &SignalOutgoing) -> Result<u64, String> {
+            if let Some(e) = &self.send_error {
+                return Err(e.clone());
+            }
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(message.clone());
+            Ok(1_700_000_000_000 + sent.len() as u64)
+        }
+        async fn receive(&self) -> Result<Vec<SignalEvent>, String> {
+            Ok(std::mem::take(&mut *self.events.lock().unwrap()))
+        }
+        async fn fetch_attachment(
+            &self,
+            id: &str,
+            _source: &SignalTarget,
+        ) -> Result<Vec<u8>, String> {
+            Ok(format!("data of {id}").into_bytes())
+        }
+        async fn list_groups(&self) -> Result<Vec<(String, String)>, String> {
+            Ok(vec![("Z3JvdXA=".into(), "Family".into())])
+        }
     }
 
-    #[tokio::test]
-    async fn test_signal_connect() {
-        let config = SignalConfig {
+    fn incoming(text: &str) -> SignalIncoming {
+        SignalIncoming {
+            sender: "+1234567890".into(),
+            sender_name: Some("Alice".into()),
+            text: text.into(),
+            timestamp: 1000,
+            group_id: None,
+            attachments: Vec::new(),
+        }
+    }
+
+    fn config() -> SignalConfig {
+        SignalConfig {
             phone_number: "+1234567890".into(),
-            signal_cli_path: "/usr/bin/signal-cli".into(),
+            min_send_interval_ms: 0,
             ..Default::default()
-        };
-        let mut ch = SignalChannel::new(config, Box::new(MockSignalBridge::new(true)));
+        }
+    }
+
+    fn channel(bridge: &Arc<MockSignalBridge>, config: SignalConfig) -> SignalChannel {
+        SignalChannel::new(config, Box::new(bridge.clone()))
+    }
+
+    #[tokio::test]
+    async fn test_signal_connect() {
+        let bridge = Arc::new(MockSignalBridge::new(true));
+        let mut ch = channel(&bridge, config());
         ch.connect().await.unwrap();
         assert!(ch.is_connected());
     }
 
     #[tokio::test]
     async fn test_signal_connect_not_registered() {
-        let config = SignalConfig {
-            phone_number: "+1234567890".into(),
-            ..Default::default()
-        };
-        let mut ch = SignalChannel::new(config, Box::new(MockSignalBridge::new(false)));
+        let bridge = Arc::new(MockSignalBridge::new(false));
+        let mut ch = channel(&bridge, config());
         assert!(ch.connect().await.is_err());
     }
 
+    #[tokio::test]
+    async fn test_signal_refuses_old_signal_cli() {
+        let bridge = Arc::new(MockSignalBridge {
+            version: "0.11.5".into(),
+            ..MockSignalBridge::new(true)
+        });
+        let mut ch = channel(&bridge, config());
+        let err = ch.connect().await.unwrap_err().to_string();
+        assert!(err.contains("older than the minimum supported 0.13.0"));
+        assert!(!ch.is_connected());
+
+        assert_eq!(
+            parse_version("signal-cli 0.13.4-SNAPSHOT"),
+            Some((0, 13, 4))
+        );
+        assert!(check_version("0.14").is_ok());
+    }
+
     #[tokio::test]
     async fn test_signal_receive() {
-        let config = SignalConfig {
-            phone_number: "+1234567890".into(),
-            ..Default::default()
-        };
-        let mut ch = SignalChannel::new(config, Box::new(MockSignalBridge::new(true)));
+        let bridge = Arc::new(MockSignalBridge::new(true));
+        let mut ch = channel(&bridge, config());
         ch.connect().await.unwrap();
 
         let msgs = ch.receive_messages().await.unwrap();
         assert_eq!(msgs.len(), 1);
         assert_eq!(msgs[0].content.as_text(), Some("hello signal"));
+        assert_eq!(msgs[0].channel_id, "+1234567890");
+        assert_eq!(msgs[0].id, MessageId::new("1000"));
+        assert!(msgs[0].thread_id.is_none());
     }
 
-    #[test]
-    fn test_signal_capabilities() {
-        let ch = SignalChannel::new(
+    #[tokio::test]
+    async fn test_signal_group_message_threads_and_reply_routes_to_group() {
+        let bridge = Arc::new(MockSignalBridge::new(true));
+        let mut message = incoming("dinner at 7?");
+        message.group_id = Some("Z3JvdXA=".into());
+        *bridge.events.lock().unwrap() = vec![SignalEvent::Message(message)];
+        let ch = channel(&bridge, config());
+
+        let msgs = ch.receive_messages().await.unwrap();
+        assert_eq!(msgs[0].channel_id, "group:Z3JvdXA=");
+        assert_eq!(msgs[0].thread_id, Some(ThreadId::new("group:Z3JvdXA=")));
+        assert_eq!(
+            msgs[0].metadata.get("group_name").map(String::as_str),
+            Some("Family")
+        );
+
+        let reply = ChannelMessage::text(
+            ChannelType::Signal,
+            "+1234567890",
+            ChannelUser::new("rustant", ChannelType::Signal),
+            "sounds good",
+        )
+        .with_thread(msgs[0].thread_id.clone().unwrap());
+        ch.send_message(reply).await.unwrap();
+        assert_eq!(
+            bridge.sent.lock().unwrap()[0].target,
+            SignalTarget::Group("Z3JvdXA=".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_signal_attachments_saved_to_inbox() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let bridge = Arc::new(MockSignalBridge::new(true));
+        let mut message = incoming("see photo");
+        message.attachments = vec![SignalAttachment {
+            id: "att1".into(),
+            content_type: "image/jpeg".into(),
+            filename: Some("../beach.jpg".into()),
+            size: Some(12),
+        }];
+        *bridge.events.lock().unwrap() = vec![SignalEvent::Message(message)];
+        let ch = channel(
+            &bridge,
             SignalConfig {
-                phone_number: "+1".into(),
-                ..Default::default()
+                attachments_dir: dir.path().to_path_buf(),
+                ..config()
+            },
+        );
+
+        let msgs = ch.receive_messages().await.unwrap();
+        assert_eq!(msgs.len(), 2);
+        let MessageContent::Image { url, .. } = &msgs[1].content else {
+            panic!("expected image content");
+        };
+        let path = PathBuf::from(url);
+        assert!(path.starts_with(dir.path()));
+        assert_eq!(std::fs::read(&path).unwrap(), b"data of att1");
+        assert_eq!(msgs[0].metadata.get("attachments"), Some(url));
+        assert_eq!(msgs[1].reply_to, Some(MessageId::new("1000")));
+    }
+
+    #[tokio::test]
+    async fn test_signal_receipts_update_delivery_status() {
+        let bridge = Arc::new(MockSignalBridge::new(true));
+        *bridge.events.lock().unwrap() = Vec::new();
+        let ch = channel(&bridge, config());
+        let msg = ChannelMessage::text(
+            ChannelType::Signal,
+            "+1987654321",
+            ChannelUser::new("rustant", ChannelType::Signal),
+            "on my way",
+        );
+        let id = ch.send_message(msg).await.unwrap();
+        assert_eq!(ch.delivery_status(&id), Some(DeliveryStatus::Sent));
+
+        let receipt = |status| {
+            SignalEvent::Receipt(SignalReceipt {
+                sender: "+1987654321".into(),
+                status,
+                timestamps: vec![id.0.parse().unwrap()],
+            })
+        };
+        *bridge.events.lock().unwrap() = vec![
+            receipt(DeliveryStatus::Read),
+            receipt(DeliveryStatus::Delivered),
+        ];
+        assert!(ch.receive_messages().await.unwrap().is_empty());
+        assert_eq!(ch.delivery_status(&id), Some(DeliveryStatus::Read));
+    }
+
+    #[tokio::test]
+    async fn test_signal_rate_limits_sends() {
+        let bridge = Arc::new(MockSignalBridge::new(true));
+        let ch = channel(
+            &bridge,
+            SignalConfig {
+                max_sends_per_minute: 2,
+                ..config()
             },
-            Box::new(MockSignalBridge::new(true)),
         );
+        let msg = || {
+            ChannelMessage::text(
+                ChannelType::Signal,
+                "+1987654321",
+                ChannelUser::new("rustant", ChannelType::Signal),
+                "ping",
+            )
+        };
+        ch.send_message(msg()).await.unwrap();
+        ch.send_message(msg()).await.unwrap();
+        let err = ch.send_message(msg()).await.unwrap_err().to_string();
+        assert!(err.contains("rate limited"));
+        assert_eq!(bridge.sent.lock().unwrap().len(), 2);
+
+        // A server-side rate limit pauses sending.
+        let limited = Arc::new(MockSignalBridge {
+            send_error: Some("RateLimitException: Rate limit exceeded".into()),
+            ..MockSignalBridge::new(true)
+        });
+        let ch = channel(&limited, config());
+        assert!(ch.send_message(msg()).await.is_err());
+        let mut throttle = ch.throttle.lock().unwrap();
+        assert!(throttle.reserve(Instant::now()).is_none());
+    }
+
+    #[test]
+    fn test_send_throttle_spaces_sends() {
+        let mut throttle = SendThrottle::new(&SignalConfig {
+            min_send_interval_ms: 1500,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        assert_eq!(throttle.reserve(now), Some(Duration::ZERO));
+        assert_eq!(throttle.reserve(now), Some(Duration::from_millis(1500)));
+        assert_eq!(
+            throttle.reserve(now + Duration::from_secs(10)),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_parse_envelope() {
+        let message = json!({
+            "sourceNumber": "+1234567890",
+            "sourceName": "Alice",
+            "timestamp": 1700000000000u64,
+            "dataMessage": {
+                "timestamp": 1700000000000u64,
+                "message": "hi",
+                "groupInfo": { "groupId": "Z3JvdXA=", "type": "DELIVER" },
+                "attachments": [{ "id": "att1", "contentType": "audio/aac", "size": 42 }]
+            }
+        });
+        let Some(SignalEvent::Message(parsed)) = parse_envelope(&message) else {
+            panic!("expected a message");
+        };
+        assert_eq!(parsed.group_id.as_deref(), Some("Z3JvdXA="));
+        assert_eq!(parsed.attachments[0].content_type, "audio/aac");
+
+        let receipt = json!({
+            "source": "+1234567890",
+            "receiptMessage": { "isDelivery": true, "isRead": false, "timestamps": [1, 2] }
+        });
+        assert_eq!(
+            parse_envelope(&receipt),
+            Some(SignalEvent::Receipt(SignalReceipt {
+                sender: "+1234567890".into(),
+                status: DeliveryStatus::Delivered,
+                timestamps: vec![1, 2],
+            }))
+        );
+
+        let typing = json!({ "source": "+1", "typingMessage": { "action": "STARTED" } });
+        assert!(parse_envelope(&typing).is_none());
+    }
+
+    #[test]
+    fn test_signal_capabilities() {
+        let bridge = Arc::new(MockSignalBridge::new(true));
+        let ch = channel(&bridge, config());
         let caps = ch.capabilities();
         assert!(!caps.supports_threads);
         assert!(caps.supports_files);
@@ -328,13 +1377,11 @@ mod tests {
 
     #[test]
     fn test_signal_streaming_mode() {
-        let ch = SignalChannel::new(
-            SignalConfig::default(),
-            Box::new(MockSignalBridge::new(true)),
-        );
+        let bridge = Arc::new(MockSignalBridge::new(true));
+        let ch = channel(&bridge, SignalConfig::default());
         assert_eq!(
             ch.streaming_mode(),
-            StreamingMode::Polling { interval_ms: 5000 }
+            StreamingMode::Polling { interval_ms: 1000 }
         );
     }
 }
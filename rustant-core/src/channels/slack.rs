@@ -5,13 +5,19 @@
 
 use super::{
     Channel, ChannelCapabilities, ChannelMessage, ChannelStatus, ChannelType, ChannelUser,
-    MessageId, StreamingMode,
+    MessageId, StreamingMode, ThreadId,
 };
 use crate::error::{ChannelError, RustantError};
 use crate::oauth::AuthMethod;
 use crate::secret_ref::{SecretRef, SecretResolver};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Replies requested per thread history fetch. Slack returns replies oldest
+/// first, so the latest ones are taken from this page.
+const THREAD_REPLIES_PAGE: usize = 200;
 
 /// Configuration for a Slack channel.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -32,8 +38,8 @@ pub struct SlackConfig {
 impl SlackConfig {
     /// Resolve the bot token from its `SecretRef` to an actual token string.
     pub fn resolve_bot_token(&self) -> Result<String, crate::secret_ref::SecretResolveError> {
-        let store = crate::credentials::KeyringCredentialStore::new();
-        SecretResolver::resolve(&self.bot_token, &store)
+        let store = crate::credentials::configured_store();
+        SecretResolver::resolve(&self.bot_token, store.as_ref())
     }
 }
 
@@ -132,6 +138,15 @@ pub trait SlackHttpClient: Send + Sync {
         channel: &str,
         limit: usize,
     ) -> Result<Vec<SlackMessage>, String>;
+    /// Messages in the thread started by `ts`, oldest first. With `oldest`,
+    /// only replies posted after that timestamp.
+    async fn conversations_replies(
+        &self,
+        channel: &str,
+        ts: &str,
+        oldest: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SlackMessage>, String>;
     async fn auth_test(&self) -> Result<String, String>;
 
     // Channels
@@ -179,6 +194,8 @@ pub struct SlackChannel {
     status: ChannelStatus,
     http_client: Box<dyn SlackHttpClient>,
     name: String,
+    /// Display names of users already looked up, for history transcripts.
+    user_names: Mutex<HashMap<String, String>>,
 }
 
 impl SlackChannel {
@@ -188,6 +205,7 @@ impl SlackChannel {
             status: ChannelStatus::Disconnected,
             http_client,
             name: "slack".to_string(),
+            user_names: Mutex::new(HashMap::new()),
         }
     }
 
@@ -357,6 +375,55 @@ impl SlackChannel {
             })
     }
 
+    /// Convert an API message, keeping its `ts` as the message ID and its
+    /// thread. DM conversations (IDs starting with `D`) are marked `is_dm`.
+    fn to_channel_message(sm: &SlackMessage) -> ChannelMessage {
+        let sender = ChannelUser::new(&sm.user, ChannelType::Slack);
+        let mut msg = ChannelMessage::text(ChannelType::Slack, &sm.channel, sender, &sm.text);
+        msg.id = MessageId::new(&sm.ts);
+        if let Some(at) = parse_ts(&sm.ts) {
+            msg.timestamp = at;
+        }
+        if let Some(thread_ts) = &sm.thread_ts {
+            msg = msg.with_thread(ThreadId::new(thread_ts));
+        }
+        if sm.channel.starts_with('D') {
+            msg = msg.with_metadata("is_dm", "true");
+        }
+        msg
+    }
+
+    /// Fill in sender display names, looking each user up once.
+    async fn with_user_names(&self, mut messages: Vec<ChannelMessage>) -> Vec<ChannelMessage> {
+        for msg in &mut messages {
+            let cached = self
+                .user_names
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(&msg.sender.id)
+                .cloned();
+            let name = match cached {
+                Some(name) => name,
+                None => {
+                    let Ok(user) = self.http_client.users_info(&msg.sender.id).await else {
+                        continue;
+                    };
+                    let name = [user.display_name, user.real_name, user.name]
+                        .into_iter()
+                        .find(|n| !n.is_empty())
+                        .unwrap_or_else(|| msg.sender.id.clone());
+                    self.user_names
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(msg.sender.id.clone(), name.clone());
+                    name
+                }
+            };
+            msg.sender.display_name = Some(name);
+        }
+        messages
+    }
+
     /// Read recent messages from a channel. Wraps `conversations_history`.
     pub async fn read_history(
         &self,
@@ -450,15 +517,57 @@ impl Channel for SlackChannel {
                     })
                 })?;
 
-            for sm in slack_msgs {
-                let sender = ChannelUser::new(&sm.user, ChannelType::Slack);
-                let msg = ChannelMessage::text(ChannelType::Slack, &sm.channel, sender, &sm.text);
-                all.push(msg);
-            }
+            all.extend(slack_msgs.iter().map(Self::to_channel_message));
         }
         Ok(all)
     }
 
+    async fn thread_history(
+        &self,
+        channel_id: &str,
+        thread_id: &ThreadId,
+        after: Option<&MessageId>,
+        limit: usize,
+    ) -> Result<Vec<ChannelMessage>, RustantError> {
+        let replies = self
+            .http_client
+            .conversations_replies(
+                channel_id,
+                &thread_id.0,
+                after.map(|id| id.0.as_str()),
+                THREAD_REPLIES_PAGE,
+            )
+            .await
+            .map_err(|e| {
+                RustantError::Channel(ChannelError::ConnectionFailed {
+                    name: self.name.clone(),
+                    message: e,
+                })
+            })?;
+        // Slack always includes the parent message, even when it is older
+        // than `oldest`.
+        let newer: Vec<ChannelMessage> = replies
+            .iter()
+            .filter(|sm| after.is_none_or(|id| ts_after(&sm.ts, &id.0)))
+            .map(Self::to_channel_message)
+            .collect();
+        let skip = newer.len().saturating_sub(limit);
+        Ok(self
+            .with_user_names(newer.into_iter().skip(skip).collect())
+            .await)
+    }
+
+    async fn recent_messages(
+        &self,
+        channel_id: &str,
+        limit: usize,
+    ) -> Result<Vec<ChannelMessage>, RustantError> {
+        let history = self.read_history(channel_id, limit).await?;
+        // conversations.history returns the newest message first.
+        let messages = history.iter().rev().map(Self::to_channel_message).collect();
+        Ok(self.with_user_names(messages).await)
+    }
+
     fn status(&self) -> ChannelStatus {
         self.status
     }
@@ -481,6 +590,57 @@ impl Channel for SlackChannel {
     }
 }
 
+/// A Slack `ts` ("1700000000.000100") as a timestamp.
+fn parse_ts(ts: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let (secs, micros) = ts.split_once('.').unwrap_or((ts, "0"));
+    let nanos = format!("{:0<9}", micros).get(..9)?.parse().ok()?;
+    chrono::DateTime::from_timestamp(secs.parse().ok()?, nanos)
+}
+
+/// Whether Slack timestamp `ts` is later than `other`.
+fn ts_after(ts: &str, other: &str) -> bool {
+    let key = |t: &str| {
+        let (secs, micros) = t.split_once('.').unwrap_or((t, ""));
+        (secs.parse::<u64>().unwrap_or(0), format!("{:0<6}", micros))
+    };
+    key(ts) > key(other)
+}
+
+/// The `messages` array of a history or replies response.
+fn parse_messages(json: &serde_json::Value, channel: &str) -> Vec<SlackMessage> {
+    json.get("messages")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|msg| {
+                    let ts = msg.get("ts")?.as_str()?.to_string();
+                    let user = msg
+                        .get("user")
+                        .and_then(|u| u.as_str())
+                        .unwrap_or("unknown")
+                        .to_string();
+                    let text = msg
+                        .get("text")
+                        .and_then(|t| t.as_str())
+                        .unwrap_or("")
+                        .to_string();
+                    let thread_ts = msg
+                        .get("thread_ts")
+                        .and_then(|t| t.as_str())
+                        .map(|s| s.to_string());
+                    Some(SlackMessage {
+                        ts,
+                        channel: channel.to_string(),
+                        user,
+                        text,
+                        thread_ts,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 // ── Real HTTP client ───────────────────────────────────────────────────────
 
 /// Real Slack HTTP client using the Slack Web API via reqwest.
@@ -615,41 +775,25 @@ impl SlackHttpClient for RealSlackHttp {
             channel, limit
         );
         let json = self.slack_get(&url).await?;
+        Ok(parse_messages(&json, channel))
+    }
 
-        let messages = json
-            .get("messages")
-            .and_then(|m| m.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|msg| {
-                        let ts = msg.get("ts")?.as_str()?.to_string();
-                        let user = msg
-                            .get("user")
-                            .and_then(|u| u.as_str())
-                            .unwrap_or("unknown")
-                            .to_string();
-                        let text = msg
-                            .get("text")
-                            .and_then(|t| t.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        let thread_ts = msg
-                            .get("thread_ts")
-                            .and_then(|t| t.as_str())
-                            .map(|s| s.to_string());
-                        Some(SlackMessage {
-                            ts,
-                            channel: channel.to_string(),
-                            user,
-                            text,
-                            thread_ts,
-                        })
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        Ok(messages)
+    async fn conversations_replies(
+        &self,
+        channel: &str,
+        ts: &str,
+        oldest: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SlackMessage>, String> {
+        let mut url = format!(
+            "https://slack.com/api/conversations.replies?channel={}&ts={}&limit={}",
+            channel, ts, limit
+        );
+        if let Some(oldest) = oldest {
+            url.push_str(&format!("&oldest={}", oldest));
+        }
+        let json = self.slack_get(&url).await?;
+        Ok(parse_messages(&json, channel))
     }
 
     async fn auth_test(&self) -> Result<String, String> {
@@ -1072,6 +1216,7 @@ mod tests {
         sent: Arc<Mutex<Vec<(String, String)>>>,
         messages: Vec<SlackMessage>,
         auth_ok: bool,
+        replies_requested: Arc<Mutex<Vec<Option<String>>>>,
     }
 
     impl MockSlackHttp {
@@ -1080,6 +1225,7 @@ mod tests {
                 sent: Arc::new(Mutex::new(Vec::new())),
                 messages: Vec::new(),
                 auth_ok: true,
+                replies_requested: Arc::new(Mutex::new(Vec::new())),
             }
         }
 
@@ -1120,6 +1266,25 @@ mod tests {
             Ok(self.messages.clone())
         }
 
+        async fn conversations_replies(
+            &self,
+            _channel: &str,
+            ts: &str,
+            oldest: Option<&str>,
+            _limit: usize,
+        ) -> Result<Vec<SlackMessage>, String> {
+            self.replies_requested
+                .lock()
+                .unwrap()
+                .push(oldest.map(str::to_string));
+            Ok(self
+                .messages
+                .iter()
+                .filter(|m| m.ts == ts || m.thread_ts.as_deref() == Some(ts))
+                .cloned()
+                .collect())
+        }
+
         async fn auth_test(&self) -> Result<String, String> {
             if self.auth_ok {
                 Ok("bot-user-id".to_string())
@@ -1316,6 +1481,86 @@ mod tests {
         assert_eq!(msgs[0].content.as_text(), Some("hey"));
     }
 
+    fn slack_message(ts: &str, channel: &str, text: &str, thread_ts: Option<&str>) -> SlackMessage {
+        SlackMessage {
+            ts: ts.into(),
+            channel: channel.into(),
+            user: "U001".into(),
+            text: text.into(),
+            thread_ts: thread_ts.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slack_receive_keeps_ts_thread_and_dm() {
+        let config = SlackConfig {
+            bot_token: "xoxb-123".into(),
+            allowed_channels: vec!["D042".into()],
+            ..Default::default()
+        };
+        let http = MockSlackHttp::new().with_messages(vec![slack_message(
+            "1700000000.000200",
+            "D042",
+            "in a thread",
+            Some("1700000000.000100"),
+        )]);
+        let ch = SlackChannel::new(config, Box::new(http));
+
+        let msgs = ch.receive_messages().await.unwrap();
+        assert_eq!(msgs[0].id, MessageId::new("1700000000.000200"));
+        assert_eq!(msgs[0].thread_id, Some(ThreadId::new("1700000000.000100")));
+        assert_eq!(msgs[0].timestamp.timestamp(), 1_700_000_000);
+        assert_eq!(
+            msgs[0].metadata.get("is_dm").map(String::as_str),
+            Some("true")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slack_thread_history_incremental() {
+        let http = MockSlackHttp::new().with_messages(vec![
+            slack_message("100.000001", "C1", "parent", Some("100.000001")),
+            slack_message("100.000002", "C1", "first reply", Some("100.000001")),
+            slack_message("100.000003", "C1", "second reply", Some("100.000001")),
+            slack_message("100.000004", "C1", "elsewhere", None),
+        ]);
+        let requested = http.replies_requested.clone();
+        let ch = SlackChannel::new(SlackConfig::default(), Box::new(http));
+        let thread = ThreadId::new("100.000001");
+
+        let all = ch.thread_history("C1", &thread, None, 10).await.unwrap();
+        let texts: Vec<_> = all.iter().filter_map(|m| m.content.as_text()).collect();
+        assert_eq!(texts, vec!["parent", "first reply", "second reply"]);
+        assert_eq!(all[0].sender.display_name.as_deref(), Some("alice"));
+
+        let latest = ch.thread_history("C1", &thread, None, 1).await.unwrap();
+        assert_eq!(latest[0].content.as_text(), Some("second reply"));
+
+        // The parent comes back from Slack but is older than the cursor.
+        let after = MessageId::new("100.000002");
+        let newer = ch
+            .thread_history("C1", &thread, Some(&after), 10)
+            .await
+            .unwrap();
+        assert_eq!(newer.len(), 1);
+        assert_eq!(newer[0].content.as_text(), Some("second reply"));
+        assert_eq!(
+            requested.lock().unwrap().last().cloned(),
+            Some(Some("100.000002".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_slack_ts_helpers() {
+        let at = parse_ts("1700000000.000100").unwrap();
+        assert_eq!(at.timestamp(), 1_700_000_000);
+        assert_eq!(at.timestamp_subsec_micros(), 100);
+        assert!(parse_ts("not-a-ts").is_none());
+        assert!(ts_after("100.000010", "100.000009"));
+        assert!(ts_after("101.1", "100.999999"));
+        assert!(!ts_after("100.000001", "100.000001"));
+    }
+
     #[test]
     fn test_slack_capabilities() {
         let ch = SlackChannel::new(SlackConfig::default(), Box::new(MockSlackHttp::new()));
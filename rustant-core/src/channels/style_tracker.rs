@@ -3,6 +3,8 @@
 //! Analyzes message patterns per-sender to learn communication preferences:
 //! message length, formality, emoji usage, common greetings, and topics.
 //! Every N messages, generates `Fact` entries for long-term memory.
+//! Profiles are keyed by sender ID and linked to the sender's
+//! [`Person`](crate::people::Person) in the workspace person graph.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,6 +14,9 @@ use std::collections::HashMap;
 pub struct SenderStyleProfile {
     /// Sender identifier (e.g., Slack user ID).
     pub sender_id: String,
+    /// The sender's id in the person graph, once resolved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub person_id: Option<usize>,
     /// Channel type (e.g., "slack", "email").
     pub channel_type: String,
     /// Total messages analyzed.
@@ -137,6 +142,21 @@ impl CommunicationStyleTracker {
         self.profiles.get(sender_id)
     }
 
+    /// Link a sender's profile to a person in the person graph.
+    pub fn link_person(&mut self, sender_id: &str, person_id: usize) {
+        if let Some(profile) = self.profiles.get_mut(sender_id) {
+            profile.person_id = Some(person_id);
+        }
+    }
+
+    /// Profiles of every sender linked to a person, across channels.
+    pub fn profiles_for_person(&self, person_id: usize) -> Vec<&SenderStyleProfile> {
+        self.profiles
+            .values()
+            .filter(|p| p.person_id == Some(person_id))
+            .collect()
+    }
+
     /// Get all tracked profiles.
     pub fn all_profiles(&self) -> &HashMap<String, SenderStyleProfile> {
         &self.profiles
@@ -8,13 +8,16 @@ use super::{
     MessageId, StreamingMode,
 };
 use crate::error::{ChannelError, RustantError};
+use crate::secret_ref::{SecretRef, SecretResolver};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 /// Configuration for a Telegram channel.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TelegramConfig {
-    pub bot_token: String,
+    /// Supports `SecretRef` format: `"keychain:telegram_bot_token"`,
+    /// `"env:TELEGRAM_BOT_TOKEN"`, or inline plaintext (deprecated).
+    pub bot_token: SecretRef,
     pub allowed_chat_ids: Vec<i64>,
     pub polling_timeout_secs: u64,
 }
@@ -22,13 +25,21 @@ pub struct TelegramConfig {
 impl Default for TelegramConfig {
     fn default() -> Self {
         Self {
-            bot_token: String::new(),
+            bot_token: SecretRef::default(),
             allowed_chat_ids: Vec::new(),
             polling_timeout_secs: 30,
         }
     }
 }
 
+impl TelegramConfig {
+    /// Resolve the bot token from its `SecretRef` to an actual token string.
+    pub fn resolve_bot_token(&self) -> Result<String, crate::secret_ref::SecretResolveError> {
+        let store = crate::credentials::configured_store();
+        SecretResolver::resolve(&self.bot_token, store.as_ref())
+    }
+}
+
 /// Trait for HTTP interactions, allowing test mocking.
 #[async_trait]
 pub trait TelegramHttpClient: Send + Sync {
@@ -257,7 +268,14 @@ impl TelegramHttpClient for RealTelegramHttp {
 
 /// Create a Telegram channel with a real HTTP client.
 pub fn create_telegram_channel(config: TelegramConfig) -> TelegramChannel {
-    let http = RealTelegramHttp::new(&config.bot_token);
+    let resolved_token = config.resolve_bot_token().unwrap_or_else(|e| {
+        tracing::warn!(
+            "Failed to resolve Telegram bot token: {}. Falling back to raw value.",
+            e
+        );
+        config.bot_token.as_str().to_string()
+    });
+    let http = RealTelegramHttp::new(&resolved_token);
     TelegramChannel::new(config, Box::new(http))
 }
 
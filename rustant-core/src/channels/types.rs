@@ -23,6 +23,32 @@ pub enum ChannelType {
     IMessage,
 }
 
+impl ChannelType {
+    /// All channel types.
+    pub const ALL: [ChannelType; 13] = [
+        Self::Telegram,
+        Self::Discord,
+        Self::Slack,
+        Self::WebChat,
+        Self::Matrix,
+        Self::Signal,
+        Self::WhatsApp,
+        Self::Email,
+        Self::Irc,
+        Self::Webhook,
+        Self::Sms,
+        Self::Teams,
+        Self::IMessage,
+    ];
+
+    /// Parse a channel name as printed by `Display` (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|ct| ct.to_string().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
 impl std::fmt::Display for ChannelType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -193,6 +219,18 @@ impl MessageContent {
     }
 }
 
+/// Delivery progress of a sent message, as reported by platform receipts.
+/// Ordered so a later receipt never downgrades the status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DeliveryStatus {
+    /// Accepted by the platform.
+    Sent,
+    /// Delivered to the recipient's device.
+    Delivered,
+    /// Read (or viewed) by the recipient.
+    Read,
+}
+
 /// Connection status of a channel.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChannelStatus {
@@ -28,6 +28,7 @@ use crate::channels::whatsapp::WhatsAppConfig;
 use crate::gateway::GatewayConfig;
 use crate::memory::FlushConfig;
 use crate::search::SearchConfig;
+use crate::types::RiskLevel;
 
 /// Top-level configuration for the Rustant agent.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -82,6 +83,27 @@ pub struct AgentConfig {
     /// Optional plan mode configuration.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub plan: Option<crate::plan::PlanConfig>,
+    /// Fast-path routing of trivial tasks (on by default).
+    #[serde(default)]
+    pub fast_path: crate::fast_path::FastPathConfig,
+    /// Focus blocks: Do Not Disturb, held notifications and paused jobs.
+    #[serde(default)]
+    pub focus: crate::focus::FocusConfig,
+    /// Opt-in clipboard history recorded while the gateway runs.
+    #[serde(default)]
+    pub clipboard: crate::clipboard::ClipboardConfig,
+    /// Local SQLite store of agent-loop events, queried by `rustant stats`.
+    #[serde(default)]
+    pub event_store: crate::event_store::EventStoreConfig,
+    /// Workspace indexing: extra ignores and per-directory budgets.
+    #[serde(default)]
+    pub index: crate::indexer::IndexConfig,
+    /// Session persistence settings (encryption at rest, on by default).
+    #[serde(default)]
+    pub sessions: crate::session_manager::SessionsConfig,
+    /// Optional skill installation settings (directory, trusted signing keys).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skills: Option<crate::skills::SkillsConfig>,
     /// Optional CDC (Change Data Capture) configuration for channel polling.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cdc: Option<crate::channels::cdc::CdcConfig>,
@@ -91,6 +113,82 @@ pub struct AgentConfig {
     /// Optional MCP safety policy configuration.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mcp_safety: Option<McpSafetyConfig>,
+    /// Persona definitions, merged over the built-in personas.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub personas: Vec<crate::personas::PersonaConfig>,
+    /// Persona to activate at startup (overridden by `--persona`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persona: Option<String>,
+    /// Self-update settings (release channel, check interval).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update: Option<crate::updater::UpdateConfig>,
+    /// User-defined slash commands (see also `.rustant/commands.toml`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<crate::custom_commands::CustomCommandConfig>,
+    /// Declarative tool contracts (see also `.rustant/contracts.yaml`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contracts: Vec<crate::contracts::ToolContractConfig>,
+    /// Daily / week-ahead briefing sections, schedule and delivery.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub briefing: Option<crate::briefing::BriefingConfig>,
+    /// Incident start notification and safety settings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incidents: Option<crate::incidents::IncidentsConfig>,
+    /// OpenTelemetry export of traces and metrics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telemetry: Option<crate::telemetry::TelemetryConfig>,
+    /// LLM response cache (exact and optional semantic reuse).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<crate::cache::CacheConfig>,
+    /// Log filtering; reloadable while the gateway runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logging: Option<LoggingConfig>,
+    /// Promotion gates for the model registry, keyed by stage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_registry: Option<ModelRegistryConfig>,
+    /// Where credentials live: `keyring`, `file` or `auto` (keychain, else file).
+    #[serde(default)]
+    pub credential_store: crate::credentials::CredentialStoreKind,
+    /// Encrypted credential file location and key file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_file: Option<crate::credentials::CredentialFileConfig>,
+    /// Monorepo subprojects and their commands, written by `rustant init`.
+    /// When empty, subprojects are detected from the workspace.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subprojects: Vec<crate::project_detect::Subproject>,
+}
+
+/// Log output configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// `tracing` filter for stderr output, e.g. `"warn,rustant_core::gateway=debug"`.
+    /// `-v` / `-q` take precedence at startup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+}
+
+/// Model registry configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelRegistryConfig {
+    /// Gates a version must pass to enter a stage, keyed by stage name
+    /// (`dev`, `staging`, `production`). Stages without a gate only require
+    /// the version to be in the previous stage.
+    #[serde(default)]
+    pub gates: HashMap<String, PromotionGate>,
+}
+
+/// Requirements a model card must meet for promotion.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromotionGate {
+    /// Eval metrics that must be at least the given value, e.g. `accuracy = 0.9`.
+    #[serde(default)]
+    pub min_metrics: HashMap<String, f64>,
+    /// Eval metrics that must be at most the given value, e.g. `loss = 0.2`.
+    #[serde(default)]
+    pub max_metrics: HashMap<String, f64>,
+    /// Checks the card must record as passed, e.g. `transparency`, `safety`.
+    #[serde(default)]
+    pub required_checks: Vec<String>,
 }
 
 /// Meeting recording and transcription configuration.
@@ -114,6 +212,36 @@ pub struct MeetingConfig {
     pub auto_summarize: bool,
     /// Seconds of silence before auto-stopping a recording (0 = disabled).
     pub silence_timeout_secs: u64,
+    /// Transcription provider: "openai" or "whisper-local" (on-device).
+    #[serde(default = "default_meeting_stt_provider")]
+    pub stt_provider: String,
+    /// Language hint for transcription (e.g., "en").
+    #[serde(default = "default_meeting_language")]
+    pub language: String,
+    /// Local Whisper model size, path and compute device.
+    #[serde(default)]
+    pub whisper: crate::voice::WhisperConfig,
+    /// Transcribe while recording instead of after the meeting ends.
+    #[serde(default)]
+    pub live_transcription: bool,
+    /// Label transcript segments with speakers ("Speaker 1", "Speaker 2", …).
+    #[serde(default)]
+    pub diarization: bool,
+    /// Maximum number of distinct speakers diarization assigns.
+    #[serde(default = "default_max_speakers")]
+    pub max_speakers: usize,
+}
+
+fn default_meeting_stt_provider() -> String {
+    "openai".to_string()
+}
+
+fn default_meeting_language() -> String {
+    "en".to_string()
+}
+
+fn default_max_speakers() -> usize {
+    6
 }
 
 impl Default for MeetingConfig {
@@ -128,6 +256,12 @@ impl Default for MeetingConfig {
             auto_transcribe: true,
             auto_summarize: true,
             silence_timeout_secs: 60,
+            stt_provider: default_meeting_stt_provider(),
+            language: default_meeting_language(),
+            whisper: crate::voice::WhisperConfig::default(),
+            live_transcription: false,
+            diarization: false,
+            max_speakers: default_max_speakers(),
         }
     }
 }
@@ -354,6 +488,13 @@ pub struct SchedulerConfig {
     /// Path for persisting scheduler state.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub state_path: Option<PathBuf>,
+    /// Runs kept in each cron job's execution history.
+    #[serde(default = "default_history_runs")]
+    pub history_runs: usize,
+}
+
+fn default_history_runs() -> usize {
+    20
 }
 
 impl Default for SchedulerConfig {
@@ -365,10 +506,22 @@ impl Default for SchedulerConfig {
             webhook_port: None,
             max_background_jobs: 10,
             state_path: None,
+            history_runs: default_history_runs(),
         }
     }
 }
 
+/// How voice mode starts listening for a command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceActivation {
+    /// Listen continuously for one of the `wake_words`.
+    #[default]
+    WakeWord,
+    /// Record a command each time Enter is pressed.
+    PushToTalk,
+}
+
 /// Configuration for the voice and audio system.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VoiceConfig {
@@ -390,6 +543,9 @@ pub struct VoiceConfig {
     pub vad_enabled: bool,
     /// VAD energy threshold (0.0-1.0).
     pub vad_threshold: f32,
+    /// How voice mode starts listening for a command.
+    #[serde(default)]
+    pub activation: VoiceActivation,
     /// Wake word phrases (e.g., ["hey rustant"]).
     #[serde(default)]
     pub wake_words: Vec<String>,
@@ -405,6 +561,40 @@ pub struct VoiceConfig {
     /// Audio output device name (None = system default).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_device: Option<String>,
+    /// Seconds to keep listening for a follow-up without the wake word
+    /// after a spoken reply (0 = always require the wake word).
+    #[serde(default = "default_voice_follow_up_secs")]
+    pub follow_up_secs: u64,
+    /// Turns in one voice conversation before the wake word is needed again.
+    #[serde(default = "default_voice_max_turns")]
+    pub max_turns: u32,
+    /// Stop speaking when the user talks over a reply and treat their
+    /// speech as the next turn.
+    #[serde(default = "default_true")]
+    pub barge_in: bool,
+    /// Phrases that approve a risky action by voice. Anything else, or
+    /// silence, denies it.
+    #[serde(default = "default_voice_confirm_phrases")]
+    pub confirm_phrases: Vec<String>,
+    /// Seconds to wait for a spoken confirmation before denying.
+    #[serde(default = "default_voice_confirm_timeout_secs")]
+    pub confirm_timeout_secs: u64,
+}
+
+fn default_voice_follow_up_secs() -> u64 {
+    8
+}
+
+fn default_voice_max_turns() -> u32 {
+    10
+}
+
+fn default_voice_confirm_phrases() -> Vec<String> {
+    vec!["confirm".to_string()]
+}
+
+fn default_voice_confirm_timeout_secs() -> u64 {
+    10
 }
 
 impl Default for VoiceConfig {
@@ -419,12 +609,18 @@ impl Default for VoiceConfig {
             tts_speed: 1.0,
             vad_enabled: true,
             vad_threshold: 0.01,
+            activation: VoiceActivation::default(),
             wake_words: vec!["hey rustant".to_string()],
             wake_sensitivity: 0.5,
             auto_speak: false,
             max_listen_secs: 30,
             input_device: None,
             output_device: None,
+            follow_up_secs: default_voice_follow_up_secs(),
+            max_turns: default_voice_max_turns(),
+            barge_in: true,
+            confirm_phrases: default_voice_confirm_phrases(),
+            confirm_timeout_secs: default_voice_confirm_timeout_secs(),
         }
     }
 }
@@ -444,6 +640,44 @@ pub struct MultiAgentConfig {
     /// Default base directory for agent workspaces.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_workspace_base: Option<String>,
+    /// Seconds a received message waits for an ack before redelivery.
+    #[serde(default = "default_visibility_timeout_secs")]
+    pub visibility_timeout_secs: u64,
+    /// Deliveries per message before it moves to the dead-letter queue.
+    #[serde(default = "default_max_delivery_attempts")]
+    pub max_delivery_attempts: u32,
+    /// Default time-to-live for messages, in seconds. `None` keeps them
+    /// until delivered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_ttl_secs: Option<u64>,
+    /// File that mailboxes are persisted to, so a restart keeps in-flight
+    /// messages. In-memory only when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mailbox_state_path: Option<String>,
+    /// Named agent roles (`[multi_agent.roles.<name>]`). A spawned agent
+    /// that declares a role only gets that role's tools.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub roles: HashMap<String, AgentRoleConfig>,
+}
+
+/// Tools and limits for agents spawned with a named role.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentRoleConfig {
+    /// Tool allowlist. Tools not listed are hidden from the agent and
+    /// refused if it calls them anyway.
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Limits for agents with this role, replacing `default_resource_limits`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_limits: Option<crate::multi::ResourceLimits>,
+}
+
+fn default_visibility_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_delivery_attempts() -> u32 {
+    3
 }
 
 impl Default for MultiAgentConfig {
@@ -454,6 +688,11 @@ impl Default for MultiAgentConfig {
             max_mailbox_size: 1000,
             default_resource_limits: crate::multi::ResourceLimits::default(),
             default_workspace_base: None,
+            visibility_timeout_secs: default_visibility_timeout_secs(),
+            max_delivery_attempts: default_max_delivery_attempts(),
+            message_ttl_secs: None,
+            mailbox_state_path: None,
+            roles: HashMap::new(),
         }
     }
 }
@@ -490,6 +729,111 @@ pub enum DigestFrequency {
     Weekly,
 }
 
+/// A section that can be included in a delivered digest.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestSection {
+    /// High and urgent messages.
+    Highlights,
+    /// Messages classified as needing action.
+    ActionItems,
+    /// Pending follow-up reminders for the source channel.
+    FollowUps,
+    /// Per-channel message counts.
+    Counts,
+}
+
+/// Template controlling what a delivered digest contains.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DigestTemplate {
+    /// Sections to include, in order.
+    #[serde(default = "default_digest_sections")]
+    pub sections: Vec<DigestSection>,
+    /// Render a single short line suitable for SMS-length channels.
+    #[serde(default)]
+    pub compact: bool,
+    /// Maximum characters for compact digests (default: 160).
+    #[serde(default = "default_compact_max_chars")]
+    pub max_chars: usize,
+}
+
+fn default_digest_sections() -> Vec<DigestSection> {
+    vec![
+        DigestSection::Highlights,
+        DigestSection::ActionItems,
+        DigestSection::FollowUps,
+        DigestSection::Counts,
+    ]
+}
+
+fn default_compact_max_chars() -> usize {
+    160
+}
+
+impl Default for DigestTemplate {
+    fn default() -> Self {
+        Self {
+            sections: default_digest_sections(),
+            compact: false,
+            max_chars: default_compact_max_chars(),
+        }
+    }
+}
+
+/// Scheduled delivery settings for a channel's digest.
+///
+/// The frequency comes from the channel's `digest` setting; this controls
+/// when and where the digest is sent and how it is rendered.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DigestDeliveryConfig {
+    /// Local delivery time in HH:MM format (default: "09:00"). For hourly
+    /// digests only the minute is used.
+    #[serde(default = "default_delivery_time")]
+    pub delivery_time: String,
+    /// IANA timezone for `delivery_time` (e.g., "Europe/Berlin"). Defaults to UTC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Day of week for weekly digests (e.g., "monday"). Defaults to Monday.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekday: Option<String>,
+    /// Channel to deliver to. Defaults to the source channel.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    /// Conversation/chat ID on the destination channel (e.g., a Slack channel ID).
+    #[serde(default)]
+    pub destination_id: String,
+    /// Rendering template.
+    #[serde(default)]
+    pub template: DigestTemplate,
+}
+
+fn default_delivery_time() -> String {
+    "09:00".to_string()
+}
+
+impl Default for DigestDeliveryConfig {
+    fn default() -> Self {
+        Self {
+            delivery_time: default_delivery_time(),
+            timezone: None,
+            weekday: None,
+            destination: None,
+            destination_id: String::new(),
+            template: DigestTemplate::default(),
+        }
+    }
+}
+
+impl DigestDeliveryConfig {
+    /// Parse the configured timezone, falling back to UTC.
+    pub fn tz(&self) -> chrono_tz::Tz {
+        self.timezone
+            .as_deref()
+            .and_then(|tz| tz.parse().ok())
+            .unwrap_or(chrono_tz::UTC)
+    }
+}
+
 /// Priority level for classifying channel messages.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
 #[serde(rename_all = "snake_case")]
@@ -502,6 +846,7 @@ pub enum MessagePriority {
     /// High priority, needs timely attention.
     High = 2,
     /// Urgent, needs immediate attention.
+    #[serde(alias = "critical")]
     Urgent = 3,
 }
 
@@ -525,6 +870,9 @@ pub struct ChannelIntelligenceConfig {
     /// Default follow-up reminder delay in minutes (default: 60).
     #[serde(default = "default_followup_minutes")]
     pub default_followup_minutes: u32,
+    /// Scheduled digest delivery; digests are only sent when this is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest_delivery: Option<DigestDeliveryConfig>,
 }
 
 impl Default for ChannelIntelligenceConfig {
@@ -535,6 +883,7 @@ impl Default for ChannelIntelligenceConfig {
             smart_scheduling: true,
             escalation_threshold: MessagePriority::High,
             default_followup_minutes: default_followup_minutes(),
+            digest_delivery: None,
         }
     }
 }
@@ -631,6 +980,37 @@ impl ChannelIntelligenceConfig {
                 .push("escalation_threshold is Low — all messages will be escalated".to_string());
         }
 
+        if let Some(ref delivery) = self.digest_delivery {
+            if !is_valid_time_format(&delivery.delivery_time) {
+                warnings.push(format!(
+                    "digest_delivery.delivery_time '{}' is not in HH:MM format",
+                    delivery.delivery_time
+                ));
+            }
+            if let Some(ref tz) = delivery.timezone
+                && tz.parse::<chrono_tz::Tz>().is_err()
+            {
+                warnings.push(format!(
+                    "digest_delivery.timezone '{}' is not a known timezone — using UTC",
+                    tz
+                ));
+            }
+            if let Some(ref day) = delivery.weekday
+                && day.parse::<chrono::Weekday>().is_err()
+            {
+                warnings.push(format!(
+                    "digest_delivery.weekday '{}' is not a weekday — using Monday",
+                    day
+                ));
+            }
+            if self.digest == DigestFrequency::Off {
+                warnings.push(
+                    "digest_delivery is set but digest frequency is off — nothing will be sent"
+                        .to_string(),
+                );
+            }
+        }
+
         warnings
     }
 }
@@ -735,6 +1115,28 @@ impl Default for RetryConfig {
     }
 }
 
+/// Per-provider request concurrency limits.
+///
+/// Requests beyond `max_concurrent` queue in FIFO order; a request that waits
+/// longer than `max_queue_wait_secs` fails with `LlmError::Saturated`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Maximum concurrent in-flight requests per provider credential (0 = unlimited).
+    pub max_concurrent: usize,
+    /// Maximum time a request may wait for a slot, in seconds.
+    pub max_queue_wait_secs: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 4,
+            max_queue_wait_secs: 120,
+        }
+    }
+}
+
 /// Configuration for messaging channels.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ChannelsConfig {
@@ -764,6 +1166,14 @@ pub struct ChannelsConfig {
     pub irc: Option<IrcConfig>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub webhook: Option<WebhookConfig>,
+    /// Rules routing incoming messages to agents.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<crate::channels::RoutingConfig>,
+    /// Conversation history included when the agent replies, keyed by
+    /// channel name (`slack`, `discord`, ...). Unlisted channels use the
+    /// defaults.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub thread_context: HashMap<String, crate::channels::ThreadContextConfig>,
 }
 
 /// LLM provider configuration.
@@ -792,6 +1202,10 @@ pub struct LlmConfig {
     /// Optional fallback providers tried in order if the primary fails.
     #[serde(default)]
     pub fallback_providers: Vec<FallbackProviderConfig>,
+    /// Vision-capable provider that describes attached images when the
+    /// primary model cannot see them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vision_fallback: Option<FallbackProviderConfig>,
     /// Optional credential store key (provider name in the OS credential store).
     /// If set, the API key is loaded from the credential store instead of the env var.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -809,6 +1223,9 @@ pub struct LlmConfig {
     /// Retry configuration for transient API errors (429, 5xx, timeouts).
     #[serde(default)]
     pub retry: RetryConfig,
+    /// Concurrency limits shared by every request using this provider credential.
+    #[serde(default)]
+    pub rate_limits: RateLimitConfig,
 }
 
 /// Configuration for a fallback LLM provider.
@@ -839,10 +1256,12 @@ impl Default for LlmConfig {
             output_cost_per_million: 10.00,
             use_streaming: true,
             fallback_providers: Vec::new(),
+            vision_fallback: None,
             credential_store_key: None,
             auth_method: String::new(),
             api_key: None,
             retry: RetryConfig::default(),
+            rate_limits: RateLimitConfig::default(),
         }
     }
 }
@@ -879,7 +1298,8 @@ pub enum ApprovalMode {
     Safe,
     /// All reversible operations are auto-approved; destructive requires approval.
     Cautious,
-    /// Every single action requires explicit approval.
+    /// Every single action requires explicit approval, and writes, network
+    /// access and shell commands are limited by `[safety.paranoid]`.
     Paranoid,
     /// All operations are auto-approved (use at own risk).
     Yolo,
@@ -923,6 +1343,20 @@ pub struct SafetyConfig {
     /// Maximum tool calls per minute (0 = unlimited).
     #[serde(default)]
     pub max_tool_calls_per_minute: usize,
+    /// Capability limits enforced in paranoid mode.
+    #[serde(default)]
+    pub paranoid: crate::capabilities::ParanoidConfig,
+    /// Bulk mail deletes touching more than this many messages always
+    /// require approval, whatever the approval mode.
+    #[serde(default = "default_mail_bulk_delete_threshold")]
+    pub mail_bulk_delete_approval_threshold: usize,
+    /// What happens to approvals nobody answers in time.
+    #[serde(default)]
+    pub approval_timeouts: crate::approval_timeout::ApprovalTimeoutConfig,
+}
+
+fn default_mail_bulk_delete_threshold() -> usize {
+    25
 }
 
 /// Configuration for the prompt injection detection system.
@@ -955,6 +1389,49 @@ pub struct AdaptiveTrustConfig {
     pub trust_escalation_threshold: usize,
     /// Anomaly score [0, 1] above which trust is de-escalated.
     pub anomaly_threshold: f64,
+    /// Per-(tool, workspace) score [0, 1] at which a tool is auto-approved.
+    #[serde(default = "default_auto_approve_score")]
+    pub auto_approve_score: f64,
+    /// Days for an unused tool's trust to halve (0 disables decay).
+    #[serde(default = "default_decay_half_life_days")]
+    pub decay_half_life_days: f64,
+    /// Score lost when the user denies a tool.
+    #[serde(default = "default_denial_penalty")]
+    pub denial_penalty: f64,
+    /// Score lost when the safety guardian blocks a tool call.
+    #[serde(default = "default_block_penalty")]
+    pub block_penalty: f64,
+    /// Score lost when a tool call fails.
+    #[serde(default = "default_failure_penalty")]
+    pub failure_penalty: f64,
+    /// Highest risk level trust may auto-approve. Values above `Write` are
+    /// clamped: execute, network and destructive actions always ask.
+    #[serde(default = "default_max_auto_approve_risk")]
+    pub max_auto_approve_risk: RiskLevel,
+}
+
+fn default_auto_approve_score() -> f64 {
+    0.8
+}
+
+fn default_decay_half_life_days() -> f64 {
+    14.0
+}
+
+fn default_denial_penalty() -> f64 {
+    0.5
+}
+
+fn default_block_penalty() -> f64 {
+    0.75
+}
+
+fn default_failure_penalty() -> f64 {
+    0.1
+}
+
+fn default_max_auto_approve_risk() -> RiskLevel {
+    RiskLevel::Write
 }
 
 impl Default for AdaptiveTrustConfig {
@@ -963,6 +1440,12 @@ impl Default for AdaptiveTrustConfig {
             enabled: true,
             trust_escalation_threshold: 5,
             anomaly_threshold: 0.7,
+            auto_approve_score: default_auto_approve_score(),
+            decay_half_life_days: default_decay_half_life_days(),
+            denial_penalty: default_denial_penalty(),
+            block_penalty: default_block_penalty(),
+            failure_penalty: default_failure_penalty(),
+            max_auto_approve_risk: default_max_auto_approve_risk(),
         }
     }
 }
@@ -1026,6 +1509,9 @@ impl Default for SafetyConfig {
             injection_detection: InjectionDetectionConfig::default(),
             adaptive_trust: None,
             max_tool_calls_per_minute: 0,
+            paranoid: crate::capabilities::ParanoidConfig::default(),
+            mail_bulk_delete_approval_threshold: default_mail_bulk_delete_threshold(),
+            approval_timeouts: Default::default(),
         }
     }
 }
@@ -1041,6 +1527,13 @@ pub struct MemoryConfig {
     pub persist_path: Option<PathBuf>,
     /// Whether to enable long-term memory persistence.
     pub enable_persistence: bool,
+    /// Most tokens of a pinned file included in each prompt.
+    #[serde(default = "default_pin_token_budget")]
+    pub pin_token_budget: usize,
+}
+
+fn default_pin_token_budget() -> usize {
+    2000
 }
 
 impl Default for MemoryConfig {
@@ -1050,6 +1543,7 @@ impl Default for MemoryConfig {
             compression_threshold: 0.7,
             persist_path: None,
             enable_persistence: true,
+            pin_token_budget: default_pin_token_budget(),
         }
     }
 }
@@ -1091,6 +1585,43 @@ pub struct ToolsConfig {
     pub default_timeout_secs: u64,
     /// Maximum output size from a tool in bytes.
     pub max_output_bytes: usize,
+    /// Settings for the `web_fetch` tool (`[tools.web_fetch]`).
+    #[serde(default)]
+    pub web_fetch: WebFetchConfig,
+    /// Check arguments against each tool's JSON Schema before execution.
+    #[serde(default = "default_true")]
+    pub validate_arguments: bool,
+    /// Tools whose arguments may be coerced during validation: numeric
+    /// strings become numbers and "true"/"false" become booleans.
+    #[serde(default)]
+    pub coerce_arguments: Vec<String>,
+    /// Automatic retries of transient tool failures (`[tools.retry]`).
+    #[serde(default)]
+    pub retry: ToolRetryConfig,
+    /// Snapshots of cited web sources (`[tools.citation_archive]`).
+    #[serde(default)]
+    pub citation_archive: CitationArchiveConfig,
+    /// List deprecated tool aliases next to their tools, so models and MCP
+    /// clients still see the old names. Calls through an alias work either
+    /// way until the alias is removed.
+    #[serde(default)]
+    pub list_aliases: bool,
+    /// Query guards for the `http_api` tool's GraphQL actions
+    /// (`[tools.graphql]`).
+    #[serde(default)]
+    pub graphql: GraphqlConfig,
+    /// Seconds after which a running tool call is reported as slow. From
+    /// then on Ctrl-C in the REPL cancels just that call. 0 disables.
+    #[serde(default = "default_slow_call_secs")]
+    pub slow_call_secs: u64,
+    /// Language-server checks of code before `file_write` and `smart_edit`
+    /// write it (`[tools.pre_write_diagnostics]`).
+    #[serde(default)]
+    pub pre_write_diagnostics: PreWriteDiagnosticsConfig,
+}
+
+fn default_slow_call_secs() -> u64 {
+    10
 }
 
 impl Default for ToolsConfig {
@@ -1099,10 +1630,205 @@ impl Default for ToolsConfig {
             enable_builtins: true,
             default_timeout_secs: 60,
             max_output_bytes: 1_048_576, // 1MB
+            web_fetch: WebFetchConfig::default(),
+            validate_arguments: true,
+            coerce_arguments: Vec::new(),
+            retry: ToolRetryConfig::default(),
+            citation_archive: CitationArchiveConfig::default(),
+            list_aliases: false,
+            graphql: GraphqlConfig::default(),
+            slow_call_secs: default_slow_call_secs(),
+            pre_write_diagnostics: PreWriteDiagnosticsConfig::default(),
+        }
+    }
+}
+
+/// Pre-write diagnostics: proposed code is shown to the language server as an
+/// in-memory overlay, and errors it introduces are sent back to the model
+/// instead of being written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreWriteDiagnosticsConfig {
+    /// Languages to check, as named by the LSP server registry ("rust",
+    /// "python", "typescript", ...). Empty disables the check; leave out
+    /// languages whose servers are too slow for every write.
+    pub languages: Vec<String>,
+    /// Milliseconds to wait for the server, including startup. When it runs
+    /// out the write goes ahead with a note.
+    pub budget_ms: u64,
+    /// Consecutive rejected writes to one file before the next write goes
+    /// through with its errors listed.
+    pub max_attempts: u32,
+}
+
+impl Default for PreWriteDiagnosticsConfig {
+    fn default() -> Self {
+        Self {
+            languages: Vec::new(),
+            budget_ms: 2000,
+            max_attempts: 3,
+        }
+    }
+}
+
+/// Cost guards for GraphQL queries sent by the `http_api` tool. Queries are
+/// checked against the endpoint's cached schema before they are sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphqlConfig {
+    /// Deepest field nesting a query may have.
+    pub max_depth: usize,
+    /// Most nodes a query may be estimated to return. Each list field
+    /// multiplies the count by its `first`/`last`/`limit` argument.
+    pub max_nodes: u64,
+    /// Items assumed for a list field without a page-size argument.
+    pub default_list_size: u64,
+    /// Largest response body read, in bytes. Larger responses are refused.
+    pub max_response_bytes: usize,
+}
+
+impl Default for GraphqlConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 12,
+            max_nodes: 10_000,
+            default_list_size: 10,
+            max_response_bytes: 256 * 1024,
+        }
+    }
+}
+
+/// Storage and change detection for the `citation_archive` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CitationArchiveConfig {
+    /// Total size of stored snapshots, in megabytes, before the least
+    /// recently used ones not cited by a saved report are evicted.
+    pub max_storage_mb: u64,
+    /// Share of a page's text (0.0–1.0) that must differ from its snapshot
+    /// for `verify` to report it as changed.
+    pub change_threshold: f64,
+}
+
+impl Default for CitationArchiveConfig {
+    fn default() -> Self {
+        Self {
+            max_storage_mb: 256,
+            change_threshold: 0.2,
+        }
+    }
+}
+
+/// Transparent retries of tool calls that fail with a transient error kind
+/// (timeout, rate limit, transient network).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolRetryConfig {
+    /// Retries of a single call before the failure reaches the model.
+    pub max_retries_per_call: u32,
+    /// Retries allowed across all calls of one task.
+    pub task_budget: u32,
+    /// First backoff delay in milliseconds; doubles on each retry.
+    pub initial_backoff_ms: u64,
+    /// Longest wait, including a tool's retry-after hint, in milliseconds.
+    pub max_backoff_ms: u64,
+}
+
+impl Default for ToolRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries_per_call: 2,
+            task_budget: 6,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+impl ToolRetryConfig {
+    /// Delay before retry number `attempt` (starting at 1), honouring a
+    /// retry-after hint up to `max_backoff_ms`.
+    pub fn backoff(&self, attempt: u32, retry_after_secs: Option<u64>) -> std::time::Duration {
+        let exponential = self
+            .initial_backoff_ms
+            .saturating_mul(1u64 << attempt.saturating_sub(1).min(16));
+        let hinted = retry_after_secs.unwrap_or(0).saturating_mul(1000);
+        std::time::Duration::from_millis(exponential.max(hinted).min(self.max_backoff_ms))
+    }
+}
+
+/// Size guards, politeness and extraction settings for `web_fetch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebFetchConfig {
+    /// User-Agent sent with every request; its first token is matched
+    /// against robots.txt groups.
+    pub user_agent: String,
+    /// Largest response body read, in bytes. Larger responses are refused.
+    pub max_response_bytes: usize,
+    /// Accepted content types. An entry ending in `/` matches a type prefix
+    /// (`text/`), one starting with `+` matches a structured-syntax suffix
+    /// (`+json`), anything else matches the exact media type.
+    pub allowed_content_types: Vec<String>,
+    /// Obey robots.txt unless a call passes `ignore_robots`.
+    pub respect_robots: bool,
+    /// Minimum delay between requests to the same host, in milliseconds.
+    /// A longer robots.txt `Crawl-delay` (capped at 10s) takes precedence.
+    pub min_host_interval_ms: u64,
+    /// Responses kept for ETag / Last-Modified revalidation.
+    pub cache_entries: usize,
+    /// Render near-empty pages in the browser session, when one is connected.
+    pub js_render: bool,
+    /// Extracted characters below which a page counts as near-empty.
+    pub min_content_chars: usize,
+}
+
+impl Default for WebFetchConfig {
+    fn default() -> Self {
+        Self {
+            user_agent: "Rustant/1.0".into(),
+            max_response_bytes: 5 * 1024 * 1024,
+            allowed_content_types: vec![
+                "text/".into(),
+                "application/xhtml+xml".into(),
+                "application/xml".into(),
+                "application/json".into(),
+                "application/javascript".into(),
+                "+xml".into(),
+                "+json".into(),
+            ],
+            respect_robots: true,
+            min_host_interval_ms: 1000,
+            cache_entries: 64,
+            js_render: false,
+            min_content_chars: 200,
         }
     }
 }
 
+impl WebFetchConfig {
+    /// Whether a `Content-Type` header value is accepted. Parameters such as
+    /// `charset` are ignored.
+    pub fn accepts_content_type(&self, content_type: &str) -> bool {
+        let media = content_type
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+        self.allowed_content_types.iter().any(|entry| {
+            let entry = entry.to_ascii_lowercase();
+            if entry.ends_with('/') {
+                media.starts_with(&entry)
+            } else if entry.starts_with('+') {
+                media.ends_with(&entry)
+            } else {
+                media == entry
+            }
+        })
+    }
+}
+
 /// Token budget configuration for cost control.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetConfig {
@@ -1114,6 +1840,14 @@ pub struct BudgetConfig {
     pub session_token_limit: usize,
     /// Whether to warn (false) or halt (true) when budget is exceeded.
     pub halt_on_exceed: bool,
+    /// Soft cap in USD on this workspace's LLM spend per calendar month
+    /// (UTC), read from the event store (0.0 = none). Crossing it warns.
+    #[serde(default)]
+    pub monthly_soft_cap_usd: f64,
+    /// Cheaper model used instead of `[llm] model` for the rest of a month
+    /// once the soft cap is crossed. `--ignore-cost-cap` keeps the usual one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub over_cap_model: Option<String>,
 }
 
 impl Default for BudgetConfig {
@@ -1123,6 +1857,8 @@ impl Default for BudgetConfig {
             task_limit_usd: 0.0,
             session_token_limit: 0,
             halt_on_exceed: false,
+            monthly_soft_cap_usd: 0.0,
+            over_cap_model: None,
         }
     }
 }
@@ -1139,6 +1875,43 @@ pub struct KnowledgeConfig {
     /// Path to the local knowledge store file.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub knowledge_path: Option<PathBuf>,
+    /// Extract durable facts from the conversation when a session closes
+    /// and before older messages are compressed.
+    #[serde(default = "default_true")]
+    pub auto_distill: bool,
+    /// Confidence from which an extracted fact is stored without review.
+    #[serde(default = "default_store_confidence")]
+    pub store_confidence: f32,
+    /// Confidence from which an extracted fact is queued for review;
+    /// facts below it are dropped.
+    #[serde(default = "default_review_confidence")]
+    pub review_confidence: f32,
+    /// Most facts stored without review in one session of a workspace.
+    #[serde(default = "default_max_learned_per_session")]
+    pub max_learned_per_session: usize,
+    /// Similarity to a known fact above which an extracted fact is a duplicate.
+    #[serde(default = "default_duplicate_similarity")]
+    pub duplicate_similarity: f32,
+    /// Model for fact extraction, on the summarization provider. Defaults
+    /// to that provider's model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distill_model: Option<String>,
+}
+
+fn default_store_confidence() -> f32 {
+    0.8
+}
+
+fn default_review_confidence() -> f32 {
+    0.5
+}
+
+fn default_max_learned_per_session() -> usize {
+    10
+}
+
+fn default_duplicate_similarity() -> f32 {
+    0.8
 }
 
 impl Default for KnowledgeConfig {
@@ -1148,6 +1921,12 @@ impl Default for KnowledgeConfig {
             max_rules: 20,
             min_entries_for_distillation: 3,
             knowledge_path: None,
+            auto_distill: true,
+            store_confidence: default_store_confidence(),
+            review_confidence: default_review_confidence(),
+            max_learned_per_session: default_max_learned_per_session(),
+            duplicate_similarity: default_duplicate_similarity(),
+            distill_model: None,
         }
     }
 }
@@ -1298,6 +2077,10 @@ pub fn load_config(
     }
 
     let mut config: AgentConfig = figment.extract().map_err(Box::new)?;
+    crate::credentials::configure_store(
+        config.credential_store,
+        config.credential_file.clone().unwrap_or_default(),
+    );
     resolve_credentials(&mut config);
     auto_migrate_channel_secrets(&mut config, workspace);
     Ok(config)
@@ -1306,8 +2089,9 @@ pub fn load_config(
 /// Resolve credential references in config.
 ///
 /// Tries these sources in order of priority:
-/// 1. `api_key` field with `"keychain:"` prefix — resolves from OS keyring by service name
-/// 2. `credential_store_key` field — resolves from OS keyring by provider name
+/// 1. `api_key` field with `"keychain:"` prefix — resolves from the configured
+///    credential store (keychain or encrypted file) by service name
+/// 2. `credential_store_key` field — resolves from the configured store by provider name
 /// 3. (At provider init time) environment variable via `api_key_env`
 ///
 /// The resolved key is stored in `config.llm.api_key` so providers can read it
@@ -1318,8 +2102,8 @@ pub fn resolve_credentials(config: &mut AgentConfig) {
     if let Some(key) = key_value
         && let Some(service) = key.strip_prefix("keychain:")
     {
-        let store = crate::credentials::KeyringCredentialStore::new();
-        match crate::credentials::CredentialStore::get_key(&store, service) {
+        let store = crate::credentials::configured_store();
+        match store.get_key(service) {
             Ok(resolved_key) => {
                 config.llm.api_key = Some(resolved_key);
                 tracing::info!("Resolved API key from keyring service: {}", service);
@@ -1335,8 +2119,8 @@ pub fn resolve_credentials(config: &mut AgentConfig) {
     if config.llm.api_key.is_none()
         && let Some(ref cs_key) = config.llm.credential_store_key
     {
-        let store = crate::credentials::KeyringCredentialStore::new();
-        match crate::credentials::CredentialStore::get_key(&store, cs_key) {
+        let store = crate::credentials::configured_store();
+        match store.get_key(cs_key) {
             Ok(resolved_key) => {
                 config.llm.api_key = Some(resolved_key);
                 tracing::info!(
@@ -1355,13 +2139,12 @@ pub fn resolve_credentials(config: &mut AgentConfig) {
     }
 }
 
-/// Auto-migrate plaintext channel secrets to the OS keychain.
+/// Auto-migrate plaintext channel secrets to the configured credential store.
 ///
 /// If `channels.slack.bot_token` contains an inline plaintext token,
 /// migrate it to the keychain and update the in-memory config to use a
 /// `keychain:` reference. Optionally rewrites the config file.
 fn auto_migrate_channel_secrets(config: &mut AgentConfig, workspace: Option<&Path>) {
-    use crate::credentials::{CredentialStore, KeyringCredentialStore};
     use crate::secret_ref::SecretRef;
 
     let needs_slack_migration = config
@@ -1375,7 +2158,7 @@ fn auto_migrate_channel_secrets(config: &mut AgentConfig, workspace: Option<&Pat
         return;
     }
 
-    let store = KeyringCredentialStore::new();
+    let store = crate::credentials::configured_store();
     let slack = config
         .channels
         .as_ref()
@@ -1414,6 +2197,109 @@ fn auto_migrate_channel_secrets(config: &mut AgentConfig, workspace: Option<&Pat
     }
 }
 
+/// Credentials Rustant may hold, as store account names paired with the
+/// environment variable that can supply each.
+///
+/// The OS keychain cannot be enumerated, so this is the list `rustant auth
+/// migrate` copies: LLM provider keys, OAuth tokens, channel secrets, and
+/// every `keychain:` / `env:` reference in the config.
+pub fn known_credentials(config: &AgentConfig) -> Vec<(String, Option<String>)> {
+    let mut known: Vec<(String, Option<String>)> = vec![
+        ("openai".into(), Some("OPENAI_API_KEY".into())),
+        ("anthropic".into(), Some("ANTHROPIC_API_KEY".into())),
+        ("gemini".into(), Some("GEMINI_API_KEY".into())),
+        (
+            config
+                .llm
+                .credential_store_key
+                .clone()
+                .unwrap_or_else(|| config.llm.provider.clone()),
+            Some(config.llm.api_key_env.clone()),
+        ),
+    ];
+    for provider in [
+        "openai",
+        "gemini",
+        "anthropic",
+        "slack",
+        "discord",
+        "teams",
+        "whatsapp",
+        "gmail",
+        "outlook",
+    ] {
+        known.push((format!("oauth:{}", provider), None));
+    }
+    for account in [
+        "channel:slack:bot_token",
+        "channel:discord:bot_token",
+        "channel:telegram:bot_token",
+        "channel:email:password",
+        "channel:matrix:access_token",
+        "channel:whatsapp:access_token",
+        "discord_bot_token",
+        "twilio_account_sid",
+        "twilio_auth_token",
+        "rustant_session_encryption",
+    ] {
+        known.push((account.into(), None));
+    }
+    if let Ok(value) = serde_json::to_value(config) {
+        collect_secret_refs(&value, &mut Vec::new(), &mut known);
+    }
+
+    // Keep the first entry per account, filling in a variable if a later one has it.
+    let mut merged: Vec<(String, Option<String>)> = Vec::new();
+    for (account, var) in known {
+        match merged.iter_mut().find(|(a, _)| *a == account) {
+            Some(entry) => {
+                if entry.1.is_none() {
+                    entry.1 = var;
+                }
+            }
+            None => merged.push((account, var)),
+        }
+    }
+    merged
+}
+
+/// Walk serialized config for `keychain:` and `env:` secret references.
+/// `env:` references under `channels.<type>.<field>` map to the account
+/// `channel:<type>:<field>` that channel setup uses.
+fn collect_secret_refs<'a>(
+    value: &'a serde_json::Value,
+    path: &mut Vec<&'a str>,
+    out: &mut Vec<(String, Option<String>)>,
+) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                path.push(key);
+                collect_secret_refs(child, path, out);
+                path.pop();
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_secret_refs(item, path, out);
+            }
+        }
+        serde_json::Value::String(s) => {
+            if let Some(account) = s.strip_prefix("keychain:") {
+                out.push((account.to_string(), None));
+            } else if let Some(var) = s.strip_prefix("env:")
+                && let ["channels", channel, field] = path.as_slice()
+            {
+                out.push((
+                    format!("channel:{}:{}", channel, field),
+                    Some(var.to_string()),
+                ));
+            }
+        }
+        _ => {}
+    }
+}
+
 /// Check whether any Rustant configuration file exists (user-level or workspace-level).
 ///
 /// Returns `true` if a config file is found at either:
@@ -1446,6 +2332,39 @@ pub fn update_channel_config(
     workspace: &std::path::Path,
     channel_name: &str,
     channel_toml: toml::Value,
+) -> anyhow::Result<std::path::PathBuf> {
+    rewrite_workspace_config(workspace, |table| {
+        // Ensure [channels] table exists
+        let channels_table = table
+            .entry("channels")
+            .or_insert_with(|| toml::Value::Table(toml::map::Map::new()));
+
+        // Set channels.<channel_name> = channel_toml
+        if let Some(ch_table) = channels_table.as_table_mut() {
+            ch_table.insert(channel_name.to_string(), channel_toml);
+        }
+    })
+}
+
+/// Update a top-level section (e.g. `voice`) in the workspace config file.
+///
+/// Like [`update_channel_config`], all other settings are preserved.
+/// Returns the path to the config file.
+pub fn update_config_section(
+    workspace: &std::path::Path,
+    section: &str,
+    section_toml: toml::Value,
+) -> anyhow::Result<std::path::PathBuf> {
+    rewrite_workspace_config(workspace, |table| {
+        table.insert(section.to_string(), section_toml);
+    })
+}
+
+/// Load `.rustant/config.toml` (or defaults), apply `edit` to its TOML table,
+/// verify the result still parses as an `AgentConfig`, and write it back.
+fn rewrite_workspace_config(
+    workspace: &std::path::Path,
+    edit: impl FnOnce(&mut toml::map::Map<String, toml::Value>),
 ) -> anyhow::Result<std::path::PathBuf> {
     let config_dir = workspace.join(".rustant");
     std::fs::create_dir_all(&config_dir)?;
@@ -1459,20 +2378,13 @@ pub fn update_channel_config(
         AgentConfig::default()
     };
 
-    // Serialize to a TOML table so we can set the channel dynamically
+    // Serialize to a TOML table so sections can be set dynamically
     let mut table: toml::Value = toml::Value::try_from(&config)?;
-
-    // Ensure [channels] table exists
-    let channels_table = table
-        .as_table_mut()
-        .ok_or_else(|| anyhow::anyhow!("config is not a TOML table"))?
-        .entry("channels")
-        .or_insert_with(|| toml::Value::Table(toml::map::Map::new()));
-
-    // Set channels.<channel_name> = channel_toml
-    if let Some(ch_table) = channels_table.as_table_mut() {
-        ch_table.insert(channel_name.to_string(), channel_toml);
-    }
+    edit(
+        table
+            .as_table_mut()
+            .ok_or_else(|| anyhow::anyhow!("config is not a TOML table"))?,
+    );
 
     // Deserialize back to verify it's valid, then write
     config = table.try_into()?;
@@ -1790,6 +2702,22 @@ allowed_hosts = []
         assert_eq!(ma.max_mailbox_size, 1000);
     }
 
+    #[test]
+    fn test_multi_agent_delivery_defaults() {
+        let ma: MultiAgentConfig = toml::from_str(
+            "enabled = true\nmax_agents = 4\nmax_mailbox_size = 50\nmessage_ttl_secs = 600\n",
+        )
+        .unwrap();
+        assert_eq!(ma.visibility_timeout_secs, 30);
+        assert_eq!(ma.max_delivery_attempts, 3);
+        assert!(ma.mailbox_state_path.is_none());
+        let policy = crate::multi::DeliveryPolicy::from_config(&ma);
+        assert_eq!(
+            policy.default_ttl,
+            Some(std::time::Duration::from_secs(600))
+        );
+    }
+
     #[test]
     fn test_injection_detection_config_defaults() {
         let config = InjectionDetectionConfig::default();
@@ -1859,6 +2787,22 @@ allowed_hosts = []
         assert!(config.default_workspace_base.is_none());
     }
 
+    #[test]
+    fn test_multi_agent_roles_from_toml() {
+        let ma: MultiAgentConfig = toml::from_str(
+            "enabled = true\nmax_agents = 4\nmax_mailbox_size = 100\n\
+             [roles.research]\ntools = [\"web_fetch\", \"web_search\"]\n\
+             [roles.research.resource_limits]\nmax_tool_calls = 20\n",
+        )
+        .unwrap();
+        let research = &ma.roles["research"];
+        assert_eq!(research.tools, vec!["web_fetch", "web_search"]);
+        assert_eq!(
+            research.resource_limits.as_ref().unwrap().max_tool_calls,
+            Some(20)
+        );
+    }
+
     #[test]
     fn test_multi_agent_config_defaults() {
         let config = MultiAgentConfig::default();
@@ -1894,6 +2838,7 @@ allowed_hosts = []
                 smart_scheduling: false,
                 escalation_threshold: MessagePriority::Urgent,
                 default_followup_minutes: 60,
+                digest_delivery: None,
             },
         );
 
@@ -1985,6 +2930,48 @@ allowed_hosts = []
         assert_eq!(quiet.end, "07:00");
     }
 
+    #[test]
+    fn test_digest_delivery_config_toml_and_validation() {
+        let toml_str = r#"
+            digest = "daily"
+
+            [digest_delivery]
+            delivery_time = "08:30"
+            timezone = "Europe/Berlin"
+            destination = "sms"
+            destination_id = "+15550100"
+
+            [digest_delivery.template]
+            sections = ["action_items", "counts"]
+            compact = true
+        "#;
+        let config: ChannelIntelligenceConfig = toml::from_str(toml_str).unwrap();
+        let delivery = config.digest_delivery.as_ref().unwrap();
+        assert_eq!(delivery.delivery_time, "08:30");
+        assert_eq!(delivery.tz(), chrono_tz::Europe::Berlin);
+        assert_eq!(
+            delivery.template.sections,
+            vec![DigestSection::ActionItems, DigestSection::Counts]
+        );
+        assert!(delivery.template.compact);
+        assert_eq!(delivery.template.max_chars, 160);
+        assert!(config.validate().is_empty());
+
+        let bad = ChannelIntelligenceConfig {
+            digest_delivery: Some(DigestDeliveryConfig {
+                delivery_time: "8am".into(),
+                timezone: Some("Mars/Olympus".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let warnings = bad.validate();
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings.iter().any(|w| w.contains("HH:MM")));
+        assert!(warnings.iter().any(|w| w.contains("timezone")));
+        assert!(warnings.iter().any(|w| w.contains("frequency is off")));
+    }
+
     #[test]
     fn test_auto_reply_mode_serde() {
         assert_eq!(
@@ -2212,4 +3199,99 @@ allowed_hosts = []
         assert!(!council.enabled);
         assert!(council.members.is_empty());
     }
+
+    #[test]
+    fn test_web_fetch_config_content_types_and_partial_toml() {
+        let config = WebFetchConfig::default();
+        assert!(config.accepts_content_type("text/html; charset=utf-8"));
+        assert!(config.accepts_content_type("application/ld+json"));
+        assert!(config.accepts_content_type("Application/XHTML+XML"));
+        assert!(!config.accepts_content_type("application/octet-stream"));
+        assert!(!config.accepts_content_type("image/png"));
+        assert!(!config.accepts_content_type(""));
+
+        let tools: ToolsConfig = toml::from_str(
+            "enable_builtins = true\ndefault_timeout_secs = 60\nmax_output_bytes = 1024\n\
+             [web_fetch]\njs_render = true\nmin_host_interval_ms = 0\n",
+        )
+        .unwrap();
+        assert!(tools.web_fetch.js_render);
+        assert_eq!(tools.web_fetch.min_host_interval_ms, 0);
+        assert!(tools.web_fetch.respect_robots);
+        assert_eq!(tools.web_fetch.user_agent, "Rustant/1.0");
+    }
+
+    #[test]
+    fn test_update_config_section_preserves_other_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let telegram = crate::channels::telegram::TelegramConfig {
+            bot_token: crate::secret_ref::SecretRef::keychain("channel:telegram:bot_token"),
+            ..Default::default()
+        };
+        update_channel_config(
+            dir.path(),
+            "telegram",
+            toml::Value::try_from(&telegram).unwrap(),
+        )
+        .unwrap();
+
+        let voice = VoiceConfig {
+            enabled: true,
+            activation: VoiceActivation::PushToTalk,
+            ..Default::default()
+        };
+        let path =
+            update_config_section(dir.path(), "voice", toml::Value::try_from(&voice).unwrap())
+                .unwrap();
+
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.contains("activation = \"push_to_talk\""));
+        let config: AgentConfig = toml::from_str(&content).unwrap();
+        let voice = config.voice.unwrap();
+        assert!(voice.enabled);
+        assert_eq!(voice.activation, VoiceActivation::PushToTalk);
+        let telegram = config.channels.unwrap().telegram.unwrap();
+        assert!(telegram.bot_token.is_keychain());
+    }
+
+    #[test]
+    fn test_known_credentials_include_config_refs() {
+        let mut config = AgentConfig::default();
+        config.llm.credential_store_key = Some("work-openai".into());
+        config.channels = Some(ChannelsConfig {
+            telegram: Some(crate::channels::telegram::TelegramConfig {
+                bot_token: crate::secret_ref::SecretRef::env("MY_TELEGRAM_TOKEN"),
+                ..Default::default()
+            }),
+            matrix: Some(crate::channels::matrix::MatrixConfig {
+                access_token: crate::secret_ref::SecretRef::keychain("matrix-bot"),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        let known = known_credentials(&config);
+        let var_for = |account: &str| {
+            known
+                .iter()
+                .find(|(a, _)| a == account)
+                .map(|(_, var)| var.clone())
+        };
+        assert_eq!(var_for("openai"), Some(Some("OPENAI_API_KEY".into())));
+        assert_eq!(
+            var_for("work-openai"),
+            Some(Some(config.llm.api_key_env.clone()))
+        );
+        assert_eq!(
+            var_for("channel:telegram:bot_token"),
+            Some(Some("MY_TELEGRAM_TOKEN".into()))
+        );
+        assert_eq!(var_for("matrix-bot"), Some(None));
+        assert_eq!(var_for("oauth:slack"), Some(None));
+        let accounts: Vec<&str> = known.iter().map(|(a, _)| a.as_str()).collect();
+        let mut deduped = accounts.clone();
+        deduped.sort();
+        deduped.dedup();
+        assert_eq!(accounts.len(), deduped.len());
+    }
 }
@@ -0,0 +1,419 @@
+//! Cost preflight: estimate what an execution plan will cost before it runs.
+//!
+//! Each plan step is keyed by kind (`reasoning` for steps the model carries
+//! out, `tool:<name>` for direct tool calls, `subtask` for decomposed
+//! sub-tasks). Per-kind averages of model calls and token volumes are learned
+//! from past runs and kept in `.rustant/cost/step_history.json`; kinds
+//! without history fall back to built-in defaults. The estimate multiplies
+//! those volumes, plus the current context size, through the model's
+//! per-token pricing. After the plan runs, a [`CostReport`] compares actual
+//! spend to the estimate and feeds the actuals back into the averages.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::plan::ExecutionPlan;
+
+/// Workspace-relative path of the per-kind history.
+const HISTORY_FILE: &str = ".rustant/cost/step_history.json";
+/// Samples after which new runs are blended in as a moving average.
+const HISTORY_WINDOW: u32 = 20;
+/// Prompt text the agent adds around a reasoning step.
+const STEP_PROMPT_TOKENS: usize = 50;
+
+/// Averages for one step kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct StepStats {
+    pub samples: u32,
+    /// Model calls per step.
+    pub llm_calls: f64,
+    /// Input tokens per call beyond the conversation context.
+    pub input_overhead: f64,
+    /// Output tokens per call.
+    pub output_tokens: f64,
+}
+
+impl StepStats {
+    const fn fixed(llm_calls: f64, input_overhead: f64, output_tokens: f64) -> Self {
+        Self {
+            samples: 0,
+            llm_calls,
+            input_overhead,
+            output_tokens,
+        }
+    }
+
+    /// Built-in averages for a kind with no history.
+    fn default_for(kind: &str) -> Self {
+        match kind {
+            // Tool steps with known arguments run without a model call.
+            k if k.starts_with("tool:") => Self::fixed(0.0, 0.0, 0.0),
+            "subtask" => Self::fixed(4.0, 1500.0, 600.0),
+            _ => Self::fixed(1.0, 800.0, 500.0),
+        }
+    }
+
+    fn blend(&mut self, llm_calls: f64, input_overhead: f64, output_tokens: f64) {
+        self.samples = self.samples.saturating_add(1);
+        let weight = 1.0 / f64::from(self.samples.min(HISTORY_WINDOW));
+        self.llm_calls += (llm_calls - self.llm_calls) * weight;
+        self.input_overhead += (input_overhead - self.input_overhead) * weight;
+        self.output_tokens += (output_tokens - self.output_tokens) * weight;
+    }
+}
+
+/// Whether a step kind's calls carry the shared conversation context.
+fn uses_context(kind: &str) -> bool {
+    kind == "reasoning"
+}
+
+/// Per-kind averages learned from past plan runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StepHistory {
+    #[serde(default)]
+    pub kinds: BTreeMap<String, StepStats>,
+}
+
+impl StepHistory {
+    pub fn path(workspace: &Path) -> PathBuf {
+        workspace.join(HISTORY_FILE)
+    }
+
+    /// Load the history, or an empty one if none is stored yet.
+    pub fn load(workspace: &Path) -> Self {
+        std::fs::read_to_string(Self::path(workspace))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, workspace: &Path) -> std::io::Result<()> {
+        let path = Self::path(workspace);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Averages for a kind, and whether they come from history.
+    fn stats(&self, kind: &str) -> (StepStats, bool) {
+        match self.kinds.get(kind) {
+            Some(stats) if stats.samples > 0 => (*stats, true),
+            _ => (StepStats::default_for(kind), false),
+        }
+    }
+
+    /// Fold a finished step into the averages.
+    pub fn record(&mut self, actual: &StepActual) {
+        let calls = actual.llm_calls as f64;
+        let per_call = |tokens: usize| {
+            if actual.llm_calls == 0 {
+                0.0
+            } else {
+                tokens as f64 / calls
+            }
+        };
+        let overhead = (per_call(actual.input_tokens) - actual.context_tokens as f64).max(0.0);
+        self.kinds.entry(actual.kind.clone()).or_default().blend(
+            calls,
+            overhead,
+            per_call(actual.output_tokens),
+        );
+    }
+}
+
+/// Kind key of a plan step.
+pub fn step_kind(step: &crate::plan::PlanStep) -> String {
+    match &step.tool {
+        Some(tool) => format!("tool:{}", tool),
+        None => "reasoning".to_string(),
+    }
+}
+
+/// Estimated volume for one step (or one sub-task).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepEstimate {
+    /// Plan step index; sub-tasks are numbered after the steps.
+    pub index: usize,
+    pub kind: String,
+    pub llm_calls: f64,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub cost_usd: f64,
+    /// Whether the averages came from past runs rather than defaults.
+    pub from_history: bool,
+}
+
+/// Preflight estimate for a whole plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanEstimate {
+    pub model: String,
+    pub context_tokens: usize,
+    pub steps: Vec<StepEstimate>,
+    pub llm_calls: usize,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub cost_usd: f64,
+}
+
+impl PlanEstimate {
+    /// Estimate `plan` for a model priced at `(input, output)` USD per token,
+    /// starting from a conversation of `context_tokens`.
+    pub fn for_plan(
+        plan: &ExecutionPlan,
+        history: &StepHistory,
+        context_tokens: usize,
+        rates: (f64, f64),
+        model: &str,
+    ) -> Self {
+        let kinds = plan
+            .steps
+            .iter()
+            .map(|step| (step.index, step_kind(step)))
+            .chain((0..plan.subtasks.len()).map(|i| (plan.steps.len() + i, "subtask".to_string())));
+        let mut context = context_tokens;
+        let mut steps = Vec::new();
+        for (index, kind) in kinds {
+            let (stats, from_history) = history.stats(&kind);
+            let carried = if uses_context(&kind) { context } else { 0 };
+            let input = (stats.llm_calls * (carried as f64 + stats.input_overhead)).round();
+            let output = (stats.llm_calls * stats.output_tokens).round();
+            if uses_context(&kind) {
+                // The step prompt and the reply stay in the conversation.
+                context += STEP_PROMPT_TOKENS + output as usize;
+            }
+            steps.push(StepEstimate {
+                index,
+                kind,
+                llm_calls: stats.llm_calls,
+                input_tokens: input as usize,
+                output_tokens: output as usize,
+                cost_usd: input * rates.0 + output * rates.1,
+                from_history,
+            });
+        }
+        Self {
+            model: model.to_string(),
+            context_tokens,
+            llm_calls: steps.iter().map(|s| s.llm_calls).sum::<f64>().ceil() as usize,
+            input_tokens: steps.iter().map(|s| s.input_tokens).sum(),
+            output_tokens: steps.iter().map(|s| s.output_tokens).sum(),
+            cost_usd: steps.iter().map(|s| s.cost_usd).sum(),
+            steps,
+        }
+    }
+
+    /// Hard budget for unattended runs: the estimate times `margin`.
+    pub fn budget(&self, margin: f64) -> f64 {
+        (self.cost_usd * margin.max(1.0)).max(0.01)
+    }
+
+    /// One-line summary, e.g. "about $1.80, ~15 model calls".
+    pub fn summary(&self) -> String {
+        let learned = self.steps.iter().filter(|s| s.from_history).count();
+        format!(
+            "about ${:.2}, ~{} model call(s), ~{} input / ~{} output tokens on {} \
+             ({} of {} step(s) from past runs)",
+            self.cost_usd,
+            self.llm_calls,
+            self.input_tokens,
+            self.output_tokens,
+            self.model,
+            learned,
+            self.steps.len()
+        )
+    }
+}
+
+/// Decision on a plan whose estimate was presented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostDecision {
+    /// Run the plan.
+    Proceed,
+    /// Return to plan review to change the plan.
+    Adjust,
+    /// Cancel the task.
+    Cancel,
+    /// Nobody is present to confirm; run under a hard budget derived from
+    /// the estimate.
+    Unattended,
+}
+
+/// What one step actually used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepActual {
+    pub index: usize,
+    pub kind: String,
+    pub llm_calls: usize,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub cost_usd: f64,
+    /// Conversation context when the step started.
+    pub context_tokens: usize,
+}
+
+/// Actual spend measured against the preflight estimate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostReport {
+    pub estimate: PlanEstimate,
+    pub actual: Vec<StepActual>,
+    /// Hard budget the run was held to, if it was unattended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_usd: Option<f64>,
+}
+
+impl CostReport {
+    pub fn new(estimate: PlanEstimate, budget_usd: Option<f64>) -> Self {
+        Self {
+            estimate,
+            actual: Vec::new(),
+            budget_usd,
+        }
+    }
+
+    pub fn actual_cost(&self) -> f64 {
+        self.actual.iter().map(|a| a.cost_usd).sum()
+    }
+
+    pub fn actual_calls(&self) -> usize {
+        self.actual.iter().map(|a| a.llm_calls).sum()
+    }
+
+    /// Whether the hard budget, if any, is spent.
+    pub fn budget_exhausted(&self) -> bool {
+        self.budget_usd
+            .is_some_and(|budget| self.actual_cost() >= budget)
+    }
+
+    /// Accuracy in [0, 1]: 1 when actual matches the estimate exactly.
+    pub fn accuracy(&self) -> f64 {
+        let (est, act) = (self.estimate.cost_usd, self.actual_cost());
+        if est.max(act) <= f64::EPSILON {
+            return 1.0;
+        }
+        1.0 - (est - act).abs() / est.max(act)
+    }
+
+    /// Accuracy report appended to the session after the plan runs.
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "Cost report: estimated ${:.4} / ~{} call(s), actual ${:.4} / {} call(s) — {:.0}% accurate",
+            self.estimate.cost_usd,
+            self.estimate.llm_calls,
+            self.actual_cost(),
+            self.actual_calls(),
+            self.accuracy() * 100.0
+        );
+        if let Some(budget) = self.budget_usd {
+            out.push_str(&format!(" (budget ${:.4})", budget));
+        }
+        for actual in self.actual.iter().filter(|a| a.llm_calls > 0) {
+            let estimated = self
+                .estimate
+                .steps
+                .iter()
+                .find(|s| s.index == actual.index)
+                .map_or(0.0, |s| s.cost_usd);
+            out.push_str(&format!(
+                "\n  {} {}: estimated ${:.4}, actual ${:.4}",
+                actual.index + 1,
+                actual.kind,
+                estimated,
+                actual.cost_usd
+            ));
+        }
+        out
+    }
+
+    /// Fold the actuals into `history`.
+    pub fn update_history(&self, history: &mut StepHistory) {
+        for actual in &self.actual {
+            history.record(actual);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::PlanStep;
+
+    fn plan() -> ExecutionPlan {
+        let mut plan = ExecutionPlan::new("refactor", "two steps");
+        plan.steps = vec![
+            PlanStep {
+                index: 0,
+                description: "read the module".into(),
+                tool: Some("file_read".into()),
+                ..Default::default()
+            },
+            PlanStep {
+                index: 1,
+                description: "propose changes".into(),
+                ..Default::default()
+            },
+            PlanStep {
+                index: 2,
+                description: "summarize".into(),
+                ..Default::default()
+            },
+        ];
+        plan
+    }
+
+    #[test]
+    fn test_estimate_uses_defaults_context_and_pricing() {
+        let rates = (3.0 / 1e6, 15.0 / 1e6);
+        let est = PlanEstimate::for_plan(&plan(), &StepHistory::default(), 10_000, rates, "m");
+        assert_eq!(est.llm_calls, 2);
+        assert_eq!(est.steps[0].input_tokens, 0);
+        // First reasoning step: context + overhead; the second also carries
+        // the first step's prompt and reply.
+        assert_eq!(est.steps[1].input_tokens, 10_800);
+        assert_eq!(est.steps[2].input_tokens, 10_800 + 550);
+        assert_eq!(est.output_tokens, 1000);
+        let expected = (10_800.0 + 11_350.0) * rates.0 + 1000.0 * rates.1;
+        assert!((est.cost_usd - expected).abs() < 1e-9);
+        assert!(est.steps.iter().all(|s| !s.from_history));
+        assert!((est.budget(1.5) - expected * 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_report_feeds_history_and_persists() {
+        let rates = (1.0 / 1e6, 1.0 / 1e6);
+        let est = PlanEstimate::for_plan(&plan(), &StepHistory::default(), 1000, rates, "m");
+        let mut report = CostReport::new(est, Some(0.002));
+        report.actual.push(StepActual {
+            index: 1,
+            kind: "reasoning".into(),
+            llm_calls: 2,
+            input_tokens: 6000,
+            output_tokens: 400,
+            cost_usd: 0.0064,
+            context_tokens: 1000,
+        });
+        assert!(report.budget_exhausted());
+        let estimated = (1800.0 + 2350.0 + 1000.0) / 1e6;
+        assert!((report.accuracy() - (1.0 - (0.0064 - estimated) / 0.0064)).abs() < 1e-9);
+        assert!(report.render().contains("budget $0.0020"));
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut history = StepHistory::load(dir.path());
+        report.update_history(&mut history);
+        history.save(dir.path()).unwrap();
+
+        let loaded = StepHistory::load(dir.path());
+        let stats = loaded.kinds["reasoning"];
+        assert_eq!(stats.samples, 1);
+        assert_eq!(stats.llm_calls, 2.0);
+        assert_eq!(stats.input_overhead, 2000.0);
+        assert_eq!(stats.output_tokens, 200.0);
+
+        let next = PlanEstimate::for_plan(&plan(), &loaded, 1000, rates, "m");
+        assert!(next.steps[1].from_history);
+        assert_eq!(next.llm_calls, 4);
+    }
+}
@@ -205,77 +205,42 @@ impl PlanningCouncil {
     }
 
     /// Stage 1: Send the question to all members concurrently.
+    ///
+    /// Members rejected with `LlmError::Saturated` (their provider's request
+    /// queue was full) are retried one at a time once the concurrent round has
+    /// released its slots, rather than being dropped.
     async fn stage_query(&self, question: &str) -> Result<Vec<CouncilMemberResponse>, LlmError> {
+        let max_tokens = self.config.max_member_tokens;
         let futures: Vec<_> = self
             .members
             .iter()
-            .map(|(provider, cfg)| {
-                let provider = Arc::clone(provider);
-                let model = cfg.model.clone();
-                let provider_name = cfg.provider.clone();
-                let max_tokens = self.config.max_member_tokens;
-                let question = question.to_string();
-
-                async move {
-                    let start = Instant::now();
-                    let request = CompletionRequest {
-                        messages: vec![
-                            Message::system(
-                                "You are a council member deliberating on a planning question. \
-                                 Provide your best analysis with concrete, actionable recommendations.",
-                            ),
-                            Message::user(&question),
-                        ],
-                        tools: None,
-                        temperature: 0.7,
-                        max_tokens: Some(max_tokens),
-                        stop_sequences: vec![],
-                        model: Some(model.clone()),
-                    };
-
-                    let result = provider.complete(request).await;
-                    let latency_ms = start.elapsed().as_millis() as u64;
-
-                    match result {
-                        Ok(response) => {
-                            let (cost_in, cost_out) = provider.cost_per_token();
-                            let cost = (response.usage.input_tokens as f64 * cost_in)
-                                + (response.usage.output_tokens as f64 * cost_out);
-                            let text = response
-                                .message
-                                .content
-                                .as_text()
-                                .unwrap_or("")
-                                .to_string();
-
-                            Ok(CouncilMemberResponse {
-                                model_name: model,
-                                provider: provider_name,
-                                response_text: text,
-                                usage: response.usage,
-                                cost,
-                                latency_ms,
-                            })
-                        }
-                        Err(e) => {
-                            warn!(
-                                model = model.as_str(),
-                                error = %e,
-                                "Council member failed to respond"
-                            );
-                            Err(e)
-                        }
-                    }
-                }
-            })
+            .map(|(provider, cfg)| query_member(provider, cfg, question, max_tokens))
             .collect();
 
         let results = futures::future::join_all(futures).await;
 
-        // Collect successful responses; warn about failures.
+        // Collect successful responses; serialize saturated members; warn about failures.
         let mut responses = Vec::new();
-        for result in results {
+        let mut saturated = Vec::new();
+        for (idx, result) in results.into_iter().enumerate() {
             match result {
+                Ok(resp) => responses.push(resp),
+                Err(LlmError::Saturated { .. }) => saturated.push(idx),
+                Err(e) => {
+                    warn!(error = %e, "Skipping failed council member");
+                }
+            }
+        }
+
+        if !saturated.is_empty() {
+            info!(
+                members = saturated.len(),
+                "Provider saturated; querying remaining council members sequentially"
+            );
+        }
+        for idx in saturated {
+            let (provider, cfg) = &self.members[idx];
+            match query_member(provider, cfg, question, max_tokens).await {
                 Ok(resp) => responses.push(resp),
                 Err(e) => {
                     warn!(error = %e, "Skipping failed council member");
@@ -449,6 +414,59 @@ impl PlanningCouncil {
     }
 }
 
+/// Ask a single council member the planning question.
+async fn query_member(
+    provider: &Arc<dyn LlmProvider>,
+    cfg: &CouncilMemberConfig,
+    question: &str,
+    max_tokens: usize,
+) -> Result<CouncilMemberResponse, LlmError> {
+    let start = Instant::now();
+    let request = CompletionRequest {
+        messages: vec![
+            Message::system(
+                "You are a council member deliberating on a planning question. \
+                 Provide your best analysis with concrete, actionable recommendations.",
+            ),
+            Message::user(question),
+        ],
+        tools: None,
+        temperature: 0.7,
+        max_tokens: Some(max_tokens),
+        stop_sequences: vec![],
+        model: Some(cfg.model.clone()),
+    };
+
+    let result = provider.complete(request).await;
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(response) => {
+            let (cost_in, cost_out) = provider.cost_per_token();
+            let cost = (response.usage.input_tokens as f64 * cost_in)
+                + (response.usage.output_tokens as f64 * cost_out);
+            let text = response.message.content.as_text().unwrap_or("").to_string();
+
+            Ok(CouncilMemberResponse {
+                model_name: cfg.model.clone(),
+                provider: cfg.provider.clone(),
+                response_text: text,
+                usage: response.usage,
+                cost,
+                latency_ms,
+            })
+        }
+        Err(e) => {
+            warn!(
+                model = cfg.model.as_str(),
+                error = %e,
+                "Council member failed to respond"
+            );
+            Err(e)
+        }
+    }
+}
+
 /// Parse a peer review response into structured data.
 fn parse_peer_review(reviewer_model: &str, reviewed_index: usize, text: &str) -> PeerReview {
     let mut score: u8 = 5;
@@ -932,6 +950,86 @@ mod tests {
         assert!(!result.synthesis.is_empty());
     }
 
+    /// Provider that takes a while to answer, so concurrent members overlap.
+    struct SlowProvider(MockLlmProvider);
+
+    #[async_trait::async_trait]
+    impl LlmProvider for SlowProvider {
+        async fn complete(
+            &self,
+            request: CompletionRequest,
+        ) -> Result<crate::types::CompletionResponse, LlmError> {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            self.0.complete(request).await
+        }
+
+        async fn complete_streaming(
+            &self,
+            request: CompletionRequest,
+            tx: tokio::sync::mpsc::Sender<crate::types::StreamEvent>,
+        ) -> Result<(), LlmError> {
+            self.0.complete_streaming(request, tx).await
+        }
+
+        fn estimate_tokens(&self, messages: &[Message]) -> usize {
+            self.0.estimate_tokens(messages)
+        }
+
+        fn context_window(&self) -> usize {
+            self.0.context_window()
+        }
+
+        fn supports_tools(&self) -> bool {
+            self.0.supports_tools()
+        }
+
+        fn cost_per_token(&self) -> (f64, f64) {
+            self.0.cost_per_token()
+        }
+
+        fn model_name(&self) -> &str {
+            self.0.model_name()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_council_serializes_saturated_members() {
+        use crate::providers::{LimitedProvider, ProviderLimiter};
+
+        // One shared slot and a queue wait shorter than a single request.
+        let limiter = Arc::new(ProviderLimiter::new(
+            "council-test",
+            1,
+            std::time::Duration::from_millis(10),
+        ));
+        let members = ["model-a", "model-b", "model-c"]
+            .iter()
+            .map(|model| {
+                let provider = Arc::new(LimitedProvider::new(
+                    Arc::new(SlowProvider(MockLlmProvider::with_response("ok"))),
+                    Arc::clone(&limiter),
+                )) as Arc<dyn LlmProvider>;
+                (
+                    provider,
+                    CouncilMemberConfig {
+                        model: model.to_string(),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+        let config = CouncilConfig {
+            enabled: true,
+            enable_peer_review: false,
+            ..Default::default()
+        };
+        let council = PlanningCouncil::new(members, config).unwrap();
+
+        let responses = council.stage_query("Compare approaches").await.unwrap();
+        assert_eq!(responses.len(), 3);
+        assert!(limiter.stats().saturated_total >= 1);
+    }
+
     #[tokio::test]
     async fn test_council_cost_tracking() {
         let provider_a =
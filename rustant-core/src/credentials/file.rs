@@ -0,0 +1,507 @@
+//! Encrypted file-based credential store.
+//!
+//! For machines without an OS keychain (headless Linux servers, containers).
+//! Credentials are kept as one AES-256-GCM encrypted JSON map. The key comes
+//! from a key file (32 random bytes, raw, hex or base64) or is derived from a
+//! passphrase with Argon2id.
+
+use super::{CredentialError, CredentialStore};
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use rand::RngCore;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use zeroize::Zeroizing;
+
+/// Environment variable naming a key file (used by daemons and services).
+pub const KEY_FILE_ENV: &str = "RUSTANT_CREDENTIAL_KEY_FILE";
+/// Environment variable holding the passphrase.
+pub const PASSPHRASE_ENV: &str = "RUSTANT_CREDENTIAL_PASSPHRASE";
+
+const FORMAT_VERSION: u32 = 1;
+// OWASP's recommended Argon2id parameters: 19 MiB, 2 passes, 1 lane.
+const ARGON2_M_COST: u32 = 19_456;
+const ARGON2_T_COST: u32 = 2;
+const ARGON2_P_COST: u32 = 1;
+
+/// Settings for the encrypted credential file (`[credential_file]`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CredentialFileConfig {
+    /// Path of the encrypted file (default: `credentials.enc` in the data dir).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Key file to use instead of a passphrase. `RUSTANT_CREDENTIAL_KEY_FILE`
+    /// takes precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_file: Option<PathBuf>,
+}
+
+impl CredentialFileConfig {
+    /// The credential file path, defaulting to the user data directory.
+    pub fn resolved_path(&self) -> PathBuf {
+        self.path.clone().unwrap_or_else(|| {
+            directories::ProjectDirs::from("dev", "rustant", "rustant")
+                .map(|d| d.data_dir().join("credentials.enc"))
+                .unwrap_or_else(|| PathBuf::from(".rustant").join("credentials.enc"))
+        })
+    }
+
+    /// The key file to use, from the environment or the config.
+    pub fn resolved_key_file(&self) -> Option<PathBuf> {
+        std::env::var_os(KEY_FILE_ENV)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| self.key_file.clone())
+    }
+}
+
+/// Asks the user for the credential file passphrase. `creating` is true when
+/// the file does not exist yet, so the prompt can ask for confirmation.
+pub type PassphrasePrompt = fn(path: &Path, creating: bool) -> Option<String>;
+
+static PASSPHRASE_PROMPT: OnceLock<PassphrasePrompt> = OnceLock::new();
+
+/// Register the interactive passphrase prompt. The CLI installs one when it
+/// runs in a terminal; daemons rely on the environment instead.
+pub fn set_passphrase_prompt(prompt: PassphrasePrompt) {
+    let _ = PASSPHRASE_PROMPT.set(prompt);
+}
+
+/// Where the encryption key comes from.
+pub enum FileStoreKey {
+    /// A file holding 32 key bytes.
+    KeyFile(PathBuf),
+    /// A passphrase, stretched with Argon2id.
+    Passphrase(Zeroizing<String>),
+}
+
+impl FileStoreKey {
+    /// Find a key without user interaction: the key file, then
+    /// `RUSTANT_CREDENTIAL_PASSPHRASE`.
+    pub fn from_env(config: &CredentialFileConfig) -> Option<Self> {
+        if let Some(path) = config.resolved_key_file() {
+            return Some(FileStoreKey::KeyFile(path));
+        }
+        std::env::var(PASSPHRASE_ENV)
+            .ok()
+            .filter(|p| !p.is_empty())
+            .map(|p| FileStoreKey::Passphrase(Zeroizing::new(p)))
+    }
+
+    /// Find a key, prompting for a passphrase when a prompt is registered
+    /// and nothing is configured.
+    pub fn resolve(config: &CredentialFileConfig) -> Result<Self, CredentialError> {
+        if let Some(key) = Self::from_env(config) {
+            return Ok(key);
+        }
+        let path = config.resolved_path();
+        if let Some(prompt) = PASSPHRASE_PROMPT.get()
+            && let Some(passphrase) = prompt(&path, !path.exists())
+        {
+            return Ok(FileStoreKey::Passphrase(Zeroizing::new(passphrase)));
+        }
+        Err(CredentialError::KeyUnavailable {
+            message: format!(
+                "no key for {}. Set {} to a key file (e.g. `openssl rand -hex 32 > key && chmod 600 key`), \
+                 set {}, or run interactively to enter a passphrase",
+                path.display(),
+                KEY_FILE_ENV,
+                PASSPHRASE_ENV
+            ),
+        })
+    }
+}
+
+/// Header and ciphertext as stored on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EncryptedFile {
+    version: u32,
+    kdf: Kdf,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+enum Kdf {
+    Argon2id {
+        salt: String,
+        m_cost: u32,
+        t_cost: u32,
+        p_cost: u32,
+    },
+    KeyFile,
+}
+
+/// Credential store backed by an encrypted file.
+///
+/// The file is re-read on every operation, so several processes (the CLI and
+/// a daemon) can share it; writes replace it atomically.
+pub struct FileCredentialStore {
+    path: PathBuf,
+    key: Zeroizing<[u8; 32]>,
+    kdf: Kdf,
+    lock: Mutex<()>,
+}
+
+impl FileCredentialStore {
+    /// Open (or prepare to create) the credential file at `path`.
+    ///
+    /// Fails if the key does not decrypt an existing file.
+    pub fn open(path: impl Into<PathBuf>, key: FileStoreKey) -> Result<Self, CredentialError> {
+        let path = path.into();
+        let existing = read_file(&path)?;
+        let (key, kdf) = match (key, existing.as_ref().map(|f| &f.kdf)) {
+            (FileStoreKey::KeyFile(key_path), None | Some(Kdf::KeyFile)) => {
+                (read_key_file(&key_path)?, Kdf::KeyFile)
+            }
+            (FileStoreKey::Passphrase(passphrase), Some(kdf @ Kdf::Argon2id { .. })) => {
+                (derive_key(&passphrase, kdf)?, kdf.clone())
+            }
+            (FileStoreKey::Passphrase(passphrase), None) => {
+                let mut salt = [0u8; 16];
+                OsRng.fill_bytes(&mut salt);
+                let kdf = Kdf::Argon2id {
+                    salt: BASE64.encode(salt),
+                    m_cost: ARGON2_M_COST,
+                    t_cost: ARGON2_T_COST,
+                    p_cost: ARGON2_P_COST,
+                };
+                (derive_key(&passphrase, &kdf)?, kdf)
+            }
+            (FileStoreKey::KeyFile(_), Some(_)) => {
+                return Err(CredentialError::KeyUnavailable {
+                    message: format!(
+                        "{} is protected by a passphrase, not a key file; unset {} and provide the passphrase",
+                        path.display(),
+                        KEY_FILE_ENV
+                    ),
+                });
+            }
+            (FileStoreKey::Passphrase(_), Some(Kdf::KeyFile)) => {
+                return Err(CredentialError::KeyUnavailable {
+                    message: format!(
+                        "{} is encrypted with a key file; set {}",
+                        path.display(),
+                        KEY_FILE_ENV
+                    ),
+                });
+            }
+        };
+        let store = Self {
+            path,
+            key,
+            kdf,
+            lock: Mutex::new(()),
+        };
+        if let Some(file) = existing {
+            store.decrypt(&file)?;
+        }
+        Ok(store)
+    }
+
+    /// Path of the encrypted file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Names of all stored credentials.
+    pub fn accounts(&self) -> Result<Vec<String>, CredentialError> {
+        let _guard = self.lock.lock().unwrap();
+        Ok(self.load()?.into_keys().collect())
+    }
+
+    fn load(&self) -> Result<BTreeMap<String, String>, CredentialError> {
+        match read_file(&self.path)? {
+            Some(file) => self.decrypt(&file),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    fn decrypt(&self, file: &EncryptedFile) -> Result<BTreeMap<String, String>, CredentialError> {
+        let locked = |message: String| CredentialError::KeyUnavailable { message };
+        if file.version != FORMAT_VERSION {
+            return Err(locked(format!(
+                "{} has unsupported format version {}",
+                self.path.display(),
+                file.version
+            )));
+        }
+        let nonce = BASE64
+            .decode(&file.nonce)
+            .ok()
+            .filter(|n| n.len() == 12)
+            .ok_or_else(|| locked(format!("{} is corrupt (bad nonce)", self.path.display())))?;
+        let ciphertext = BASE64
+            .decode(&file.ciphertext)
+            .map_err(|_| locked(format!("{} is corrupt", self.path.display())))?;
+        let cipher = Aes256Gcm::new_from_slice(&self.key[..]).expect("32-byte key");
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+                .map_err(|_| {
+                    locked(format!(
+                        "wrong passphrase or key for {}",
+                        self.path.display()
+                    ))
+                })?,
+        );
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| locked(format!("{} is corrupt: {}", self.path.display(), e)))
+    }
+
+    fn save(&self, entries: &BTreeMap<String, String>) -> Result<(), CredentialError> {
+        let failed = |message: String| CredentialError::StoreFailed { message };
+        let plaintext =
+            Zeroizing::new(serde_json::to_vec(entries).map_err(|e| failed(e.to_string()))?);
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce);
+        let cipher = Aes256Gcm::new_from_slice(&self.key[..]).expect("32-byte key");
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_ref())
+            .map_err(|e| failed(e.to_string()))?;
+        let file = EncryptedFile {
+            version: FORMAT_VERSION,
+            kdf: self.kdf.clone(),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        let json = serde_json::to_vec_pretty(&file).map_err(|e| failed(e.to_string()))?;
+        write_private(&self.path, &json)
+            .map_err(|e| failed(format!("{}: {}", self.path.display(), e)))
+    }
+}
+
+impl CredentialStore for FileCredentialStore {
+    fn store_key(&self, provider: &str, api_key: &str) -> Result<(), CredentialError> {
+        let _guard = self.lock.lock().unwrap();
+        let mut entries = self.load()?;
+        entries.insert(provider.to_string(), api_key.to_string());
+        self.save(&entries)
+    }
+
+    fn get_key(&self, provider: &str) -> Result<String, CredentialError> {
+        let _guard = self.lock.lock().unwrap();
+        self.load()?
+            .remove(provider)
+            .ok_or_else(|| CredentialError::NotFound {
+                service: self.path.display().to_string(),
+                account: provider.to_string(),
+            })
+    }
+
+    fn delete_key(&self, provider: &str) -> Result<(), CredentialError> {
+        let _guard = self.lock.lock().unwrap();
+        let mut entries = self.load().map_err(|e| CredentialError::DeleteFailed {
+            message: e.to_string(),
+        })?;
+        if entries.remove(provider).is_some() {
+            self.save(&entries)
+                .map_err(|e| CredentialError::DeleteFailed {
+                    message: e.to_string(),
+                })?;
+        }
+        Ok(())
+    }
+
+    fn has_key(&self, provider: &str) -> bool {
+        self.get_key(provider).is_ok()
+    }
+}
+
+fn read_file(path: &Path) -> Result<Option<EncryptedFile>, CredentialError> {
+    match std::fs::read(path) {
+        Ok(bytes) => {
+            serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(|e| CredentialError::KeyUnavailable {
+                    message: format!("{} is not a credential file: {}", path.display(), e),
+                })
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(CredentialError::BackendUnavailable {
+            message: format!("{}: {}", path.display(), e),
+        }),
+    }
+}
+
+fn derive_key(passphrase: &str, kdf: &Kdf) -> Result<Zeroizing<[u8; 32]>, CredentialError> {
+    let Kdf::Argon2id {
+        salt,
+        m_cost,
+        t_cost,
+        p_cost,
+    } = kdf
+    else {
+        unreachable!("derive_key is only called for Argon2id files");
+    };
+    let invalid = |message: String| CredentialError::KeyUnavailable { message };
+    if passphrase.is_empty() {
+        return Err(invalid("the passphrase is empty".into()));
+    }
+    let salt = BASE64
+        .decode(salt)
+        .map_err(|_| invalid("credential file has a corrupt salt".into()))?;
+    let params = argon2::Params::new(*m_cost, *t_cost, *p_cost, Some(32))
+        .map_err(|e| invalid(format!("bad Argon2 parameters: {}", e)))?;
+    let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+    let mut key = Zeroizing::new([0u8; 32]);
+    argon2
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut key[..])
+        .map_err(|e| invalid(format!("key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+/// Read a key file: 32 raw bytes, or 64 hex digits, or base64 of 32 bytes.
+fn read_key_file(path: &Path) -> Result<Zeroizing<[u8; 32]>, CredentialError> {
+    let unavailable = |message: String| CredentialError::KeyUnavailable { message };
+    let bytes = Zeroizing::new(
+        std::fs::read(path)
+            .map_err(|e| unavailable(format!("key file {}: {}", path.display(), e)))?,
+    );
+    #[cfg(unix)]
+    if let Ok(meta) = std::fs::metadata(path) {
+        use std::os::unix::fs::PermissionsExt;
+        if meta.permissions().mode() & 0o077 != 0 {
+            tracing::warn!(
+                "Key file {} is readable by other users; run `chmod 600` on it",
+                path.display()
+            );
+        }
+    }
+    let decoded: Zeroizing<Vec<u8>> = if bytes.len() == 32 {
+        Zeroizing::new(bytes.to_vec())
+    } else {
+        let text = std::str::from_utf8(&bytes).unwrap_or_default().trim();
+        Zeroizing::new(
+            decode_hex(text)
+                .or_else(|| BASE64.decode(text).ok())
+                .unwrap_or_default(),
+        )
+    };
+    let key: [u8; 32] = decoded.as_slice().try_into().map_err(|_| {
+        unavailable(format!(
+            "key file {} must hold 32 bytes (raw, 64 hex digits or base64)",
+            path.display()
+        ))
+    })?;
+    Ok(Zeroizing::new(key))
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() != 64 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Write `data` to `path` atomically with owner-only permissions.
+fn write_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passphrase(p: &str) -> FileStoreKey {
+        FileStoreKey::Passphrase(Zeroizing::new(p.to_string()))
+    }
+
+    #[test]
+    fn test_passphrase_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.enc");
+        let store = FileCredentialStore::open(&path, passphrase("correct horse")).unwrap();
+        store.store_key("openai", "sk-file-123").unwrap();
+        store
+            .store_key("channel:slack:bot_token", "xoxb-1")
+            .unwrap();
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("sk-file-123"));
+        assert!(raw.contains("argon2id"));
+
+        let reopened = FileCredentialStore::open(&path, passphrase("correct horse")).unwrap();
+        assert_eq!(reopened.get_key("openai").unwrap(), "sk-file-123");
+        assert_eq!(
+            reopened.accounts().unwrap(),
+            ["channel:slack:bot_token", "openai"]
+        );
+        reopened.delete_key("openai").unwrap();
+        assert!(!store.has_key("openai"));
+    }
+
+    #[test]
+    fn test_wrong_passphrase_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.enc");
+        FileCredentialStore::open(&path, passphrase("right"))
+            .unwrap()
+            .store_key("openai", "sk")
+            .unwrap();
+        let err = FileCredentialStore::open(&path, passphrase("wrong"))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("wrong passphrase or key"), "{err}");
+    }
+
+    #[test]
+    fn test_key_file_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("key");
+        std::fs::write(&key_path, format!("{}\n", "ab".repeat(32))).unwrap();
+        let path = dir.path().join("credentials.enc");
+        let store =
+            FileCredentialStore::open(&path, FileStoreKey::KeyFile(key_path.clone())).unwrap();
+        store.store_key("anthropic", "sk-ant").unwrap();
+
+        // The same key as raw bytes opens the file too.
+        let raw_key = dir.path().join("raw.key");
+        std::fs::write(&raw_key, [0xab; 32]).unwrap();
+        let store = FileCredentialStore::open(&path, FileStoreKey::KeyFile(raw_key)).unwrap();
+        assert_eq!(store.get_key("anthropic").unwrap(), "sk-ant");
+
+        // A passphrase cannot open a key-file store.
+        let err = FileCredentialStore::open(&path, passphrase("x"))
+            .err()
+            .unwrap();
+        assert!(matches!(err, CredentialError::KeyUnavailable { .. }));
+    }
+
+    #[test]
+    fn test_short_key_file_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("key");
+        std::fs::write(&key_path, "too-short").unwrap();
+        let err =
+            FileCredentialStore::open(dir.path().join("c.enc"), FileStoreKey::KeyFile(key_path))
+                .err()
+                .unwrap();
+        assert!(err.to_string().contains("must hold 32 bytes"));
+    }
+}
@@ -0,0 +1,484 @@
+//! Credential storage for LLM provider API keys.
+//!
+//! Provides a trait-based abstraction over credential storage with these implementations:
+//! - `KeyringCredentialStore`: Uses the OS-native credential store (macOS Keychain,
+//!   Windows Credential Manager, Linux Secret Service).
+//! - `FileCredentialStore`: An encrypted file, for machines without a keychain.
+//! - `EnvCredentialStore`: Read-only view of environment variables, a migration source.
+//! - `InMemoryCredentialStore`: In-memory store for testing.
+//!
+//! `configured_store()` returns the store selected by `credential_store` in config.
+
+mod file;
+
+pub use file::{
+    CredentialFileConfig, FileCredentialStore, FileStoreKey, KEY_FILE_ENV, PASSPHRASE_ENV,
+    PassphrasePrompt, set_passphrase_prompt,
+};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Errors from credential storage operations.
+#[derive(Debug, thiserror::Error)]
+pub enum CredentialError {
+    #[error("Credential not found for {service}:{account}")]
+    NotFound { service: String, account: String },
+
+    #[error("Failed to store credential: {message}")]
+    StoreFailed { message: String },
+
+    #[error("Failed to delete credential: {message}")]
+    DeleteFailed { message: String },
+
+    #[error("Keyring backend not available: {message}")]
+    BackendUnavailable { message: String },
+
+    #[error("Credential file is locked: {message}")]
+    KeyUnavailable { message: String },
+}
+
+/// Which backend holds credentials (`credential_store` in config).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStoreKind {
+    /// The OS keychain.
+    Keyring,
+    /// The encrypted credential file.
+    File,
+    /// The OS keychain when available, otherwise the encrypted file.
+    #[default]
+    Auto,
+}
+
+/// Trait for credential storage backends.
+pub trait CredentialStore: Send + Sync {
+    /// Store an API key for the given provider.
+    fn store_key(&self, provider: &str, api_key: &str) -> Result<(), CredentialError>;
+
+    /// Retrieve the API key for the given provider.
+    fn get_key(&self, provider: &str) -> Result<String, CredentialError>;
+
+    /// Delete the API key for the given provider.
+    fn delete_key(&self, provider: &str) -> Result<(), CredentialError>;
+
+    /// Check whether a key exists for the given provider.
+    fn has_key(&self, provider: &str) -> bool;
+}
+
+impl<T: CredentialStore + ?Sized> CredentialStore for Arc<T> {
+    fn store_key(&self, provider: &str, api_key: &str) -> Result<(), CredentialError> {
+        (**self).store_key(provider, api_key)
+    }
+
+    fn get_key(&self, provider: &str) -> Result<String, CredentialError> {
+        (**self).get_key(provider)
+    }
+
+    fn delete_key(&self, provider: &str) -> Result<(), CredentialError> {
+        (**self).delete_key(provider)
+    }
+
+    fn has_key(&self, provider: &str) -> bool {
+        (**self).has_key(provider)
+    }
+}
+
+/// OS-native credential store using the `keyring` crate.
+///
+/// Stores credentials under service `"rustant"` with account names
+/// formatted as `"provider:{name}"`.
+pub struct KeyringCredentialStore {
+    service: String,
+}
+
+impl KeyringCredentialStore {
+    /// Create a new keyring-backed credential store.
+    pub fn new() -> Self {
+        Self {
+            service: "rustant".to_string(),
+        }
+    }
+
+    /// Format the account name for a given provider.
+    pub fn account_name(provider: &str) -> String {
+        format!("provider:{}", provider)
+    }
+
+    /// Whether an OS keychain backend is reachable (it is not on most
+    /// headless Linux servers).
+    pub fn is_available() -> bool {
+        match keyring::Entry::new("rustant", "availability-probe") {
+            Ok(entry) => matches!(entry.get_password(), Ok(_) | Err(keyring::Error::NoEntry)),
+            Err(_) => false,
+        }
+    }
+}
+
+impl Default for KeyringCredentialStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialStore for KeyringCredentialStore {
+    fn store_key(&self, provider: &str, api_key: &str) -> Result<(), CredentialError> {
+        let account = Self::account_name(provider);
+        let entry = keyring::Entry::new(&self.service, &account).map_err(|e| {
+            CredentialError::BackendUnavailable {
+                message: e.to_string(),
+            }
+        })?;
+        entry
+            .set_password(api_key)
+            .map_err(|e| CredentialError::StoreFailed {
+                message: e.to_string(),
+            })
+    }
+
+    fn get_key(&self, provider: &str) -> Result<String, CredentialError> {
+        let account = Self::account_name(provider);
+        let entry = keyring::Entry::new(&self.service, &account).map_err(|e| {
+            CredentialError::BackendUnavailable {
+                message: e.to_string(),
+            }
+        })?;
+        entry.get_password().map_err(|e| match e {
+            keyring::Error::NoEntry => CredentialError::NotFound {
+                service: self.service.clone(),
+                account,
+            },
+            other => CredentialError::StoreFailed {
+                message: other.to_string(),
+            },
+        })
+    }
+
+    fn delete_key(&self, provider: &str) -> Result<(), CredentialError> {
+        let account = Self::account_name(provider);
+        let entry = keyring::Entry::new(&self.service, &account).map_err(|e| {
+            CredentialError::BackendUnavailable {
+                message: e.to_string(),
+            }
+        })?;
+        entry
+            .delete_credential()
+            .map_err(|e| CredentialError::DeleteFailed {
+                message: e.to_string(),
+            })
+    }
+
+    fn has_key(&self, provider: &str) -> bool {
+        self.get_key(provider).is_ok()
+    }
+}
+
+/// Read-only view of credentials held in environment variables, keyed by
+/// store account name. Used as the source when migrating off env vars.
+pub struct EnvCredentialStore {
+    vars: HashMap<String, String>,
+}
+
+impl EnvCredentialStore {
+    /// Map each account to the environment variable holding it.
+    pub fn new(vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            vars: vars.into_iter().collect(),
+        }
+    }
+
+    /// The environment variable for `account`, if any.
+    pub fn var_for(&self, account: &str) -> Option<&str> {
+        self.vars.get(account).map(String::as_str)
+    }
+}
+
+impl CredentialStore for EnvCredentialStore {
+    fn store_key(&self, _provider: &str, _api_key: &str) -> Result<(), CredentialError> {
+        Err(CredentialError::StoreFailed {
+            message: "environment variables are read-only".into(),
+        })
+    }
+
+    fn get_key(&self, provider: &str) -> Result<String, CredentialError> {
+        self.var_for(provider)
+            .and_then(|var| std::env::var(var).ok())
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| CredentialError::NotFound {
+                service: "env".into(),
+                account: provider.to_string(),
+            })
+    }
+
+    fn delete_key(&self, _provider: &str) -> Result<(), CredentialError> {
+        Err(CredentialError::DeleteFailed {
+            message: "environment variables must be unset by hand".into(),
+        })
+    }
+
+    fn has_key(&self, provider: &str) -> bool {
+        self.get_key(provider).is_ok()
+    }
+}
+
+/// Stands in for a store that could not be opened, so every operation
+/// reports why.
+struct UnavailableCredentialStore {
+    message: String,
+}
+
+impl UnavailableCredentialStore {
+    fn error(&self) -> CredentialError {
+        CredentialError::BackendUnavailable {
+            message: self.message.clone(),
+        }
+    }
+}
+
+impl CredentialStore for UnavailableCredentialStore {
+    fn store_key(&self, _provider: &str, _api_key: &str) -> Result<(), CredentialError> {
+        Err(self.error())
+    }
+
+    fn get_key(&self, _provider: &str) -> Result<String, CredentialError> {
+        Err(self.error())
+    }
+
+    fn delete_key(&self, _provider: &str) -> Result<(), CredentialError> {
+        Err(self.error())
+    }
+
+    fn has_key(&self, _provider: &str) -> bool {
+        false
+    }
+}
+
+/// Open the store for `kind`. `Auto` prefers the OS keychain and falls back
+/// to the encrypted file with a warning.
+pub fn open_store(
+    kind: CredentialStoreKind,
+    file: &CredentialFileConfig,
+) -> Result<Arc<dyn CredentialStore>, CredentialError> {
+    match kind {
+        CredentialStoreKind::Keyring => Ok(Arc::new(KeyringCredentialStore::new())),
+        CredentialStoreKind::File => open_file_store(file),
+        CredentialStoreKind::Auto => {
+            if KeyringCredentialStore::is_available() {
+                return Ok(Arc::new(KeyringCredentialStore::new()));
+            }
+            let store = open_file_store(file).map_err(|e| CredentialError::BackendUnavailable {
+                message: format!("no OS keychain, and the fallback failed: {}", e),
+            })?;
+            tracing::warn!(
+                "OS keychain unavailable; using the encrypted credential file {}",
+                file.resolved_path().display()
+            );
+            Ok(store)
+        }
+    }
+}
+
+fn open_file_store(
+    file: &CredentialFileConfig,
+) -> Result<Arc<dyn CredentialStore>, CredentialError> {
+    let key = FileStoreKey::resolve(file)?;
+    Ok(Arc::new(FileCredentialStore::open(
+        file.resolved_path(),
+        key,
+    )?))
+}
+
+struct ConfiguredStore {
+    kind: CredentialStoreKind,
+    file: CredentialFileConfig,
+    store: Option<Arc<dyn CredentialStore>>,
+}
+
+static CONFIGURED_STORE: Mutex<Option<ConfiguredStore>> = Mutex::new(None);
+
+/// Select the store returned by [`configured_store`]. Called when the
+/// config is loaded; the store itself is opened on first use.
+pub fn configure_store(kind: CredentialStoreKind, file: CredentialFileConfig) {
+    let mut configured = CONFIGURED_STORE.lock().unwrap();
+    if matches!(&*configured, Some(c) if c.kind == kind && c.file == file) {
+        return;
+    }
+    *configured = Some(ConfiguredStore {
+        kind,
+        file,
+        store: None,
+    });
+}
+
+/// The credential store selected by `credential_store` in config.
+///
+/// If it cannot be opened (e.g. the credential file is locked and no
+/// passphrase is available) the returned store fails every operation with
+/// the reason.
+pub fn configured_store() -> Arc<dyn CredentialStore> {
+    let mut configured = CONFIGURED_STORE.lock().unwrap();
+    let configured = configured.get_or_insert_with(|| ConfiguredStore {
+        kind: CredentialStoreKind::default(),
+        file: CredentialFileConfig::default(),
+        store: None,
+    });
+    if let Some(store) = &configured.store {
+        return store.clone();
+    }
+    let store = open_store(configured.kind, &configured.file).unwrap_or_else(|e| {
+        tracing::warn!("Credential store unavailable: {}", e);
+        Arc::new(UnavailableCredentialStore {
+            message: e.to_string(),
+        })
+    });
+    configured.store = Some(store.clone());
+    store
+}
+
+/// In-memory credential store for testing.
+///
+/// Thread-safe via `Mutex<HashMap>`. Does not persist across process restarts.
+pub struct InMemoryCredentialStore {
+    store: Mutex<HashMap<String, String>>,
+}
+
+impl InMemoryCredentialStore {
+    /// Create an empty in-memory credential store.
+    pub fn new() -> Self {
+        Self {
+            store: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for InMemoryCredentialStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialStore for InMemoryCredentialStore {
+    fn store_key(&self, provider: &str, api_key: &str) -> Result<(), CredentialError> {
+        let account = KeyringCredentialStore::account_name(provider);
+        self.store
+            .lock()
+            .unwrap()
+            .insert(account, api_key.to_string());
+        Ok(())
+    }
+
+    fn get_key(&self, provider: &str) -> Result<String, CredentialError> {
+        let account = KeyringCredentialStore::account_name(provider);
+        self.store
+            .lock()
+            .unwrap()
+            .get(&account)
+            .cloned()
+            .ok_or_else(|| CredentialError::NotFound {
+                service: "rustant".to_string(),
+                account,
+            })
+    }
+
+    fn delete_key(&self, provider: &str) -> Result<(), CredentialError> {
+        let account = KeyringCredentialStore::account_name(provider);
+        self.store.lock().unwrap().remove(&account);
+        Ok(())
+    }
+
+    fn has_key(&self, provider: &str) -> bool {
+        let account = KeyringCredentialStore::account_name(provider);
+        self.store.lock().unwrap().contains_key(&account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_store() -> InMemoryCredentialStore {
+        InMemoryCredentialStore::new()
+    }
+
+    #[test]
+    fn test_store_and_retrieve_key() {
+        let store = make_store();
+        store.store_key("openai", "sk-test-123").unwrap();
+        assert_eq!(store.get_key("openai").unwrap(), "sk-test-123");
+    }
+
+    #[test]
+    fn test_get_nonexistent_key() {
+        let store = make_store();
+        let result = store.get_key("nonexistent");
+        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap_err(),
+            CredentialError::NotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn test_delete_key() {
+        let store = make_store();
+        store.store_key("anthropic", "sk-ant-test").unwrap();
+        store.delete_key("anthropic").unwrap();
+        assert!(!store.has_key("anthropic"));
+    }
+
+    #[test]
+    fn test_has_key() {
+        let store = make_store();
+        assert!(!store.has_key("openai"));
+        store.store_key("openai", "sk-test").unwrap();
+        assert!(store.has_key("openai"));
+    }
+
+    #[test]
+    fn test_overwrite_key() {
+        let store = make_store();
+        store.store_key("openai", "sk-old").unwrap();
+        store.store_key("openai", "sk-new").unwrap();
+        assert_eq!(store.get_key("openai").unwrap(), "sk-new");
+    }
+
+    #[test]
+    fn test_env_store_is_read_only() {
+        // SAFETY: test-only env var manipulation
+        unsafe { std::env::set_var("RUSTANT_TEST_ENV_STORE_KEY", "sk-env") };
+        let store = EnvCredentialStore::new([(
+            "openai".to_string(),
+            "RUSTANT_TEST_ENV_STORE_KEY".to_string(),
+        )]);
+        assert_eq!(store.get_key("openai").unwrap(), "sk-env");
+        assert!(!store.has_key("anthropic"));
+        assert!(store.store_key("openai", "x").is_err());
+        assert!(store.delete_key("openai").is_err());
+        // SAFETY: test-only env var manipulation
+        unsafe { std::env::remove_var("RUSTANT_TEST_ENV_STORE_KEY") };
+    }
+
+    #[test]
+    fn test_store_kind_deserialize() {
+        #[derive(Deserialize)]
+        struct Config {
+            credential_store: CredentialStoreKind,
+        }
+        let config: Config = toml::from_str("credential_store = \"file\"").unwrap();
+        assert_eq!(config.credential_store, CredentialStoreKind::File);
+        assert_eq!(CredentialStoreKind::default(), CredentialStoreKind::Auto);
+    }
+
+    #[test]
+    fn test_account_name_format() {
+        assert_eq!(
+            KeyringCredentialStore::account_name("openai"),
+            "provider:openai"
+        );
+        assert_eq!(
+            KeyringCredentialStore::account_name("anthropic"),
+            "provider:anthropic"
+        );
+    }
+}
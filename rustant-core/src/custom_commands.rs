@@ -0,0 +1,642 @@
+//! User-defined slash commands that expand into prompt templates.
+//!
+//! A custom command gives a long, frequently typed instruction a short name:
+//!
+//! ```toml
+//! [[commands]]
+//! name = "deploy"
+//! description = "Deploy the current branch"
+//! template = "Deploy the current branch to {env} following docs/deploy.md, run smoke tests after"
+//! args = ["env"]
+//! defaults = { env = "staging" }
+//! requires_approval_mode = "safe"
+//! ```
+//!
+//! Commands are read from `[[commands]]` in config.toml and from
+//! `.rustant/commands.toml` in the workspace; workspace entries replace config
+//! entries with the same name. Templates are validated when loaded, so a
+//! placeholder that is not a declared argument is an error rather than literal
+//! braces sent to the model. Use `{{` and `}}` for literal braces.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::ApprovalMode;
+
+/// File in the workspace `.rustant/` directory holding custom commands.
+pub const COMMANDS_FILE: &str = "commands.toml";
+
+/// Errors from loading, validating, or expanding custom commands.
+#[derive(Debug, thiserror::Error)]
+pub enum CustomCommandError {
+    #[error(
+        "Invalid command name '{name}': use letters, digits, '-' or '_' after an optional leading '/'"
+    )]
+    InvalidName { name: String },
+    #[error("Custom command '{name}' is defined more than once in {source_name}")]
+    Duplicate { name: String, source_name: String },
+    #[error("Custom command '{name}' collides with built-in command '{builtin}'")]
+    BuiltinCollision { name: String, builtin: String },
+    #[error(
+        "Custom command '{name}' references undefined placeholder '{{{placeholder}}}' (declared args: {declared})"
+    )]
+    UndefinedPlaceholder {
+        name: String,
+        placeholder: String,
+        declared: String,
+    },
+    #[error(
+        "Custom command '{name}' has an unbalanced '{brace}' in its template (use '{brace}{brace}' for a literal brace)"
+    )]
+    UnbalancedBrace { name: String, brace: char },
+    #[error("Custom command '{name}' declares argument '{arg}' more than once")]
+    DuplicateArg { name: String, arg: String },
+    #[error("Custom command '{name}' has a default for undeclared argument '{arg}'")]
+    UnknownDefault { name: String, arg: String },
+    #[error("Custom command '{name}' has an empty template")]
+    EmptyTemplate { name: String },
+    #[error("Failed to read {path}: {message}")]
+    Load { path: PathBuf, message: String },
+    #[error("Missing argument '{arg}' for {name}. Usage: {usage}")]
+    MissingArgument {
+        name: String,
+        arg: String,
+        usage: String,
+    },
+    #[error("Unknown argument '{arg}' for {name}. Usage: {usage}")]
+    UnknownArgument {
+        name: String,
+        arg: String,
+        usage: String,
+    },
+    #[error("{name} takes no arguments")]
+    UnexpectedArguments { name: String },
+    #[error("{name} requires approval mode '{required}' or stricter (current: '{current}')")]
+    ApprovalModeRequired {
+        name: String,
+        required: ApprovalMode,
+        current: ApprovalMode,
+    },
+}
+
+/// A custom command definition, as written under `[[commands]]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomCommandConfig {
+    /// Command name, with or without the leading `/`.
+    pub name: String,
+    /// Short description shown in `/help` and completion hints.
+    #[serde(default)]
+    pub description: String,
+    /// Prompt template; `{arg}` placeholders are replaced by argument values.
+    pub template: String,
+    /// Argument names in positional order. The last one takes the rest of the line.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Default values for arguments that may be omitted.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub defaults: BTreeMap<String, String>,
+    /// Only run when the session approval mode is this strict or stricter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requires_approval_mode: Option<ApprovalMode>,
+}
+
+/// Shape of `.rustant/commands.toml`.
+#[derive(Debug, Default, Deserialize)]
+struct CommandsFile {
+    #[serde(default)]
+    commands: Vec<CustomCommandConfig>,
+}
+
+/// A piece of a parsed template.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Placeholder(String),
+}
+
+/// A validated custom command.
+#[derive(Debug, Clone)]
+pub struct CustomCommand {
+    /// Name including the leading `/`.
+    pub name: String,
+    pub description: String,
+    pub args: Vec<String>,
+    pub defaults: BTreeMap<String, String>,
+    pub requires_approval_mode: Option<ApprovalMode>,
+    template: String,
+    segments: Vec<Segment>,
+}
+
+/// Rank approval modes from most to least permissive.
+fn strictness(mode: ApprovalMode) -> u8 {
+    match mode {
+        ApprovalMode::Yolo => 0,
+        ApprovalMode::Cautious => 1,
+        ApprovalMode::Safe => 2,
+        ApprovalMode::Paranoid => 3,
+    }
+}
+
+fn normalize_name(raw: &str) -> Result<String, CustomCommandError> {
+    let bare = raw.trim().strip_prefix('/').unwrap_or(raw.trim());
+    let valid = !bare.is_empty()
+        && bare
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(CustomCommandError::InvalidName {
+            name: raw.to_string(),
+        });
+    }
+    Ok(format!("/{}", bare.to_lowercase()))
+}
+
+fn parse_template(name: &str, template: &str) -> Result<Vec<Segment>, CustomCommandError> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some('{') | None => {
+                            return Err(CustomCommandError::UnbalancedBrace {
+                                name: name.to_string(),
+                                brace: '{',
+                            });
+                        }
+                        Some(c) => placeholder.push(c),
+                    }
+                }
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(Segment::Placeholder(placeholder.trim().to_string()));
+            }
+            '}' => {
+                return Err(CustomCommandError::UnbalancedBrace {
+                    name: name.to_string(),
+                    brace: '}',
+                });
+            }
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Ok(segments)
+}
+
+/// Split an argument line into words, honouring double quotes.
+fn split_words(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_word = false;
+    for c in input.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_word = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_word {
+                    words.push(std::mem::take(&mut current));
+                    has_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_word = true;
+            }
+        }
+    }
+    if has_word {
+        words.push(current);
+    }
+    words
+}
+
+impl CustomCommand {
+    /// Validate a definition: name, declared arguments, defaults, and template placeholders.
+    pub fn from_config(config: &CustomCommandConfig) -> Result<Self, CustomCommandError> {
+        let name = normalize_name(&config.name)?;
+        if config.template.trim().is_empty() {
+            return Err(CustomCommandError::EmptyTemplate { name });
+        }
+
+        let mut args: Vec<String> = Vec::with_capacity(config.args.len());
+        for arg in &config.args {
+            let arg = arg.trim().to_string();
+            if arg.is_empty()
+                || !arg
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(CustomCommandError::InvalidName { name: arg });
+            }
+            if args.contains(&arg) {
+                return Err(CustomCommandError::DuplicateArg { name, arg });
+            }
+            args.push(arg);
+        }
+        if let Some(arg) = config.defaults.keys().find(|k| !args.contains(k)) {
+            return Err(CustomCommandError::UnknownDefault {
+                name,
+                arg: arg.clone(),
+            });
+        }
+
+        let segments = parse_template(&name, &config.template)?;
+        for segment in &segments {
+            if let Segment::Placeholder(p) = segment
+                && !args.contains(p)
+            {
+                let declared = if args.is_empty() {
+                    "none".to_string()
+                } else {
+                    args.join(", ")
+                };
+                return Err(CustomCommandError::UndefinedPlaceholder {
+                    name,
+                    placeholder: p.clone(),
+                    declared,
+                });
+            }
+        }
+
+        Ok(Self {
+            name,
+            description: config.description.trim().to_string(),
+            args,
+            defaults: config.defaults.clone(),
+            requires_approval_mode: config.requires_approval_mode,
+            template: config.template.clone(),
+            segments,
+        })
+    }
+
+    /// The raw template text.
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// Usage line, e.g. `/deploy [env=staging]`.
+    pub fn usage(&self) -> String {
+        let mut usage = self.name.clone();
+        for arg in &self.args {
+            match self.defaults.get(arg) {
+                Some(default) => usage.push_str(&format!(" [{}={}]", arg, default)),
+                None => usage.push_str(&format!(" <{}>", arg)),
+            }
+        }
+        usage
+    }
+
+    /// Check the approval-mode guard against the session's current mode.
+    pub fn check_approval_mode(&self, current: ApprovalMode) -> Result<(), CustomCommandError> {
+        match self.requires_approval_mode {
+            Some(required) if strictness(current) < strictness(required) => {
+                Err(CustomCommandError::ApprovalModeRequired {
+                    name: self.name.clone(),
+                    required,
+                    current,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Expand the template with arguments typed after the command name.
+    ///
+    /// `name=value` words set arguments by name; remaining words fill the other
+    /// arguments in declaration order, with the last argument taking the rest of
+    /// the line. Omitted arguments fall back to their defaults.
+    pub fn expand(&self, input: &str) -> Result<String, CustomCommandError> {
+        let words = split_words(input);
+        if self.args.is_empty() {
+            if !words.is_empty() {
+                return Err(CustomCommandError::UnexpectedArguments {
+                    name: self.name.clone(),
+                });
+            }
+            return Ok(self.render(&BTreeMap::new()));
+        }
+
+        let mut values: BTreeMap<String, String> = BTreeMap::new();
+        let mut positional = Vec::new();
+        for word in words {
+            match word.split_once('=') {
+                Some((key, value))
+                    if !key.is_empty()
+                        && key
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+                {
+                    if !self.args.iter().any(|a| a == key) {
+                        return Err(CustomCommandError::UnknownArgument {
+                            name: self.name.clone(),
+                            arg: key.to_string(),
+                            usage: self.usage(),
+                        });
+                    }
+                    values.insert(key.to_string(), value.to_string());
+                }
+                _ => positional.push(word),
+            }
+        }
+
+        let open: Vec<&String> = self
+            .args
+            .iter()
+            .filter(|a| !values.contains_key(*a))
+            .collect();
+        let mut positional = positional.into_iter();
+        for (i, arg) in open.iter().enumerate() {
+            let value = if i + 1 == open.len() {
+                let rest: Vec<String> = positional.by_ref().collect();
+                (!rest.is_empty()).then(|| rest.join(" "))
+            } else {
+                positional.next()
+            };
+            if let Some(value) = value {
+                values.insert((*arg).clone(), value);
+            }
+        }
+        if positional.next().is_some() {
+            // Every argument was set by name, so positional words have nowhere to go.
+            return Err(CustomCommandError::UnknownArgument {
+                name: self.name.clone(),
+                arg: input.trim().to_string(),
+                usage: self.usage(),
+            });
+        }
+
+        for arg in &self.args {
+            if !values.contains_key(arg) {
+                match self.defaults.get(arg) {
+                    Some(default) => {
+                        values.insert(arg.clone(), default.clone());
+                    }
+                    None => {
+                        return Err(CustomCommandError::MissingArgument {
+                            name: self.name.clone(),
+                            arg: arg.clone(),
+                            usage: self.usage(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(self.render(&values))
+    }
+
+    fn render(&self, values: &BTreeMap<String, String>) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.as_str(),
+                Segment::Placeholder(p) => values.get(p).map(String::as_str).unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+/// The set of custom commands available to a session.
+#[derive(Debug, Clone, Default)]
+pub struct CustomCommandSet {
+    commands: Vec<CustomCommand>,
+}
+
+impl CustomCommandSet {
+    /// Validate a list of definitions. `source_name` labels duplicate errors.
+    pub fn from_configs(
+        configs: &[CustomCommandConfig],
+        source_name: &str,
+    ) -> Result<Self, CustomCommandError> {
+        let mut commands: Vec<CustomCommand> = Vec::with_capacity(configs.len());
+        for config in configs {
+            let command = CustomCommand::from_config(config)?;
+            if commands.iter().any(|c| c.name == command.name) {
+                return Err(CustomCommandError::Duplicate {
+                    name: command.name,
+                    source_name: source_name.to_string(),
+                });
+            }
+            commands.push(command);
+        }
+        Ok(Self { commands })
+    }
+
+    /// Load commands from config.toml entries and `<workspace>/.rustant/commands.toml`.
+    ///
+    /// Workspace entries replace config entries with the same name.
+    pub fn load(
+        configs: &[CustomCommandConfig],
+        workspace: &Path,
+    ) -> Result<Self, CustomCommandError> {
+        let mut set = Self::from_configs(configs, "config.toml")?;
+        let path = workspace.join(".rustant").join(COMMANDS_FILE);
+        if path.exists() {
+            let load_err = |message: String| CustomCommandError::Load {
+                path: path.clone(),
+                message,
+            };
+            let content = std::fs::read_to_string(&path).map_err(|e| load_err(e.to_string()))?;
+            let file: CommandsFile =
+                toml::from_str(&content).map_err(|e| load_err(e.to_string()))?;
+            let overrides = Self::from_configs(&file.commands, &path.display().to_string())?;
+            for command in overrides.commands {
+                set.commands.retain(|c| c.name != command.name);
+                set.commands.push(command);
+            }
+        }
+        set.commands.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(set)
+    }
+
+    /// Look up a command by name, with or without the leading `/`.
+    pub fn get(&self, name: &str) -> Option<&CustomCommand> {
+        let name = name.strip_prefix('/').unwrap_or(name).to_lowercase();
+        self.commands.iter().find(|c| c.name[1..] == name)
+    }
+
+    /// All commands, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = &CustomCommand> {
+        self.commands.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deploy() -> CustomCommandConfig {
+        CustomCommandConfig {
+            name: "deploy".into(),
+            description: "Deploy the current branch".into(),
+            template:
+                "Deploy the current branch to {env} following docs/deploy.md, run smoke tests after"
+                    .into(),
+            args: vec!["env".into()],
+            defaults: BTreeMap::new(),
+            requires_approval_mode: None,
+        }
+    }
+
+    #[test]
+    fn test_expand_positional_and_named() {
+        let cmd = CustomCommand::from_config(&deploy()).unwrap();
+        assert_eq!(cmd.name, "/deploy");
+        assert_eq!(
+            cmd.expand("production").unwrap(),
+            "Deploy the current branch to production following docs/deploy.md, run smoke tests after"
+        );
+        assert!(cmd.expand("env=qa").unwrap().contains(" to qa "));
+        assert!(matches!(
+            cmd.expand(""),
+            Err(CustomCommandError::MissingArgument { .. })
+        ));
+        assert!(matches!(
+            cmd.expand("region=eu"),
+            Err(CustomCommandError::UnknownArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn test_defaults_and_rest_of_line() {
+        let mut config = deploy();
+        config.name = "/review".into();
+        config.template = "Review {path} with focus on {focus}. {{strict}}".into();
+        config.args = vec!["path".into(), "focus".into()];
+        config.defaults.insert("focus".into(), "correctness".into());
+        let cmd = CustomCommand::from_config(&config).unwrap();
+
+        assert_eq!(cmd.usage(), "/review <path> [focus=correctness]");
+        assert_eq!(
+            cmd.expand("src/lib.rs").unwrap(),
+            "Review src/lib.rs with focus on correctness. {strict}"
+        );
+        assert_eq!(
+            cmd.expand("src/lib.rs error handling and naming").unwrap(),
+            "Review src/lib.rs with focus on error handling and naming. {strict}"
+        );
+        assert_eq!(
+            cmd.expand("\"my file.rs\" focus=tests").unwrap(),
+            "Review my file.rs with focus on tests. {strict}"
+        );
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let mut config = deploy();
+        config.template = "Deploy to {environment}".into();
+        let err = CustomCommand::from_config(&config).unwrap_err();
+        assert!(
+            matches!(err, CustomCommandError::UndefinedPlaceholder { ref placeholder, .. } if placeholder == "environment")
+        );
+        assert!(err.to_string().contains("{environment}"));
+
+        config.template = "Deploy to {env".into();
+        assert!(matches!(
+            CustomCommand::from_config(&config),
+            Err(CustomCommandError::UnbalancedBrace { brace: '{', .. })
+        ));
+
+        config.template = "Deploy to {env}".into();
+        config.defaults.insert("region".into(), "eu".into());
+        assert!(matches!(
+            CustomCommand::from_config(&config),
+            Err(CustomCommandError::UnknownDefault { .. })
+        ));
+
+        config.defaults.clear();
+        config.name = "deploy now".into();
+        assert!(matches!(
+            CustomCommand::from_config(&config),
+            Err(CustomCommandError::InvalidName { .. })
+        ));
+
+        let no_args = CustomCommandConfig {
+            args: vec![],
+            template: "Run the full test suite, then summarize failures grouped by module".into(),
+            ..deploy()
+        };
+        let cmd = CustomCommand::from_config(&no_args).unwrap();
+        assert!(cmd.expand("").is_ok());
+        assert!(matches!(
+            cmd.expand("extra"),
+            Err(CustomCommandError::UnexpectedArguments { .. })
+        ));
+    }
+
+    #[test]
+    fn test_approval_mode_guard() {
+        let mut config = deploy();
+        config.requires_approval_mode = Some(ApprovalMode::Safe);
+        let cmd = CustomCommand::from_config(&config).unwrap();
+        assert!(cmd.check_approval_mode(ApprovalMode::Safe).is_ok());
+        assert!(cmd.check_approval_mode(ApprovalMode::Paranoid).is_ok());
+        assert!(cmd.check_approval_mode(ApprovalMode::Cautious).is_err());
+        assert!(cmd.check_approval_mode(ApprovalMode::Yolo).is_err());
+    }
+
+    #[test]
+    fn test_load_merges_workspace_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".rustant")).unwrap();
+        std::fs::write(
+            dir.path().join(".rustant").join(COMMANDS_FILE),
+            r#"
+[[commands]]
+name = "deploy"
+description = "Deploy via workspace pipeline"
+template = "Deploy to {env} with the workspace pipeline"
+args = ["env"]
+defaults = { env = "staging" }
+
+[[commands]]
+name = "tests"
+description = "Run tests and group failures"
+template = "Run the full test suite, then summarize failures grouped by module"
+"#,
+        )
+        .unwrap();
+
+        let set = CustomCommandSet::load(&[deploy()], dir.path()).unwrap();
+        assert_eq!(set.len(), 2);
+        let names: Vec<&str> = set.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["/deploy", "/tests"]);
+        let cmd = set.get("deploy").unwrap();
+        assert_eq!(cmd.description, "Deploy via workspace pipeline");
+        assert_eq!(
+            cmd.expand("").unwrap(),
+            "Deploy to staging with the workspace pipeline"
+        );
+        assert!(set.get("/TESTS").is_some());
+
+        let dup = CustomCommandSet::from_configs(&[deploy(), deploy()], "config.toml");
+        assert!(matches!(dup, Err(CustomCommandError::Duplicate { .. })));
+    }
+}
@@ -0,0 +1,424 @@
+//! Distillation of durable facts from a session's conversation.
+//!
+//! When a session closes, and before older messages are compressed away,
+//! the agent asks the summarization route for facts worth keeping: project
+//! conventions, key file locations, decisions and user preferences, each
+//! with a confidence. Confident facts are stored in the workspace's
+//! `.rustant/knowledge/learned.json` and loaded into long-term memory in
+//! later sessions; less confident ones wait in its review queue until
+//! accepted or rejected with `/learned`. Facts similar to ones already known
+//! are skipped, and a per-session cap keeps one session from flooding the
+//! store.
+
+use crate::config::KnowledgeConfig;
+use crate::memory::Fact;
+use crate::search::SimpleEmbedder;
+use crate::similarity::{SimilarityConfig, SimilarityService};
+use crate::types::Message;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Dimensions of the bag-of-words vectors compared for duplicates.
+const SIMILARITY_DIMENSIONS: usize = 256;
+
+/// Learned facts listed in the system prompt at most, newest first.
+const MAX_PROMPT_FACTS: usize = 30;
+
+/// What a learned fact is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FactCategory {
+    /// A convention the project follows.
+    Convention,
+    /// Where something important lives.
+    Location,
+    /// A decision made during the session.
+    Decision,
+    /// A preference the user expressed.
+    Preference,
+}
+
+impl std::fmt::Display for FactCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            FactCategory::Convention => "convention",
+            FactCategory::Location => "location",
+            FactCategory::Decision => "decision",
+            FactCategory::Preference => "preference",
+        };
+        f.write_str(name)
+    }
+}
+
+/// When facts were extracted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistillTrigger {
+    /// The session was closed.
+    SessionClose,
+    /// Older messages were about to be compressed.
+    Compaction,
+}
+
+impl std::fmt::Display for DistillTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DistillTrigger::SessionClose => f.write_str("session close"),
+            DistillTrigger::Compaction => f.write_str("compaction"),
+        }
+    }
+}
+
+/// A fact proposed by the extraction model.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CandidateFact {
+    pub content: String,
+    pub category: FactCategory,
+    pub confidence: f32,
+}
+
+/// Where a learned fact came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Agent session the fact was extracted from.
+    pub session_id: Uuid,
+    /// Model that extracted it.
+    pub model: String,
+    pub trigger: DistillTrigger,
+    pub learned_at: DateTime<Utc>,
+}
+
+/// A fact extracted from a session, stored or awaiting review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LearnedFact {
+    pub id: Uuid,
+    pub content: String,
+    pub category: FactCategory,
+    pub confidence: f32,
+    pub provenance: Provenance,
+}
+
+impl LearnedFact {
+    /// The long-term memory fact for this learned fact. Its `source` is
+    /// `learned:<id>`, so it is loaded into memory only once.
+    pub fn to_fact(&self) -> Fact {
+        Fact {
+            id: self.id,
+            content: self.content.clone(),
+            source: format!("learned:{}", self.id),
+            created_at: self.provenance.learned_at,
+            tags: vec!["learned".to_string(), self.category.to_string()],
+        }
+    }
+}
+
+/// Learned facts of a workspace, persisted in
+/// `.rustant/knowledge/learned.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LearnedFacts {
+    /// Facts stored automatically or accepted from review.
+    #[serde(default)]
+    pub facts: Vec<LearnedFact>,
+    /// Facts waiting for the user to accept or reject them.
+    #[serde(default)]
+    pub review: Vec<LearnedFact>,
+}
+
+impl LearnedFacts {
+    fn path(workspace: &Path) -> PathBuf {
+        workspace
+            .join(".rustant")
+            .join("knowledge")
+            .join("learned.json")
+    }
+
+    /// Load learned facts from disk.
+    pub fn load(workspace: &Path) -> Self {
+        std::fs::read_to_string(Self::path(workspace))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist learned facts to disk (atomic write).
+    pub fn save(&self, workspace: &Path) -> Result<(), String> {
+        let path = Self::path(workspace);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Create knowledge dir: {}", e))?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Serialize learned facts: {}", e))?;
+        std::fs::write(&tmp, &json).map_err(|e| format!("Write learned facts: {}", e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| format!("Rename learned facts: {}", e))?;
+        Ok(())
+    }
+
+    /// Move a fact from the review queue to the stored facts.
+    pub fn accept(&mut self, id: Uuid) -> Option<&LearnedFact> {
+        let index = self.review.iter().position(|f| f.id == id)?;
+        let fact = self.review.remove(index);
+        self.facts.push(fact);
+        self.facts.last()
+    }
+
+    /// Drop a fact from the review queue.
+    pub fn reject(&mut self, id: Uuid) -> Option<LearnedFact> {
+        let index = self.review.iter().position(|f| f.id == id)?;
+        Some(self.review.remove(index))
+    }
+
+    /// Stored facts formatted for the system prompt, or an empty string.
+    pub fn for_prompt(&self) -> String {
+        if self.facts.is_empty() {
+            return String::new();
+        }
+        let mut prompt = String::from(
+            "\n\n## Project Knowledge\n\
+             Learned from earlier conversations in this workspace:\n",
+        );
+        for fact in self.facts.iter().rev().take(MAX_PROMPT_FACTS) {
+            prompt.push_str(&format!("- ({}) {}\n", fact.category, fact.content));
+        }
+        prompt
+    }
+}
+
+/// What one distillation pass did with the extracted facts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DistillOutcome {
+    /// Facts stored without review.
+    pub stored: Vec<LearnedFact>,
+    /// Facts queued for review.
+    pub queued: Vec<LearnedFact>,
+    /// Facts skipped as similar to known ones.
+    pub duplicates: usize,
+    /// Facts dropped for low confidence.
+    pub dropped: usize,
+}
+
+impl DistillOutcome {
+    /// Add another pass's results to these.
+    pub fn merge(&mut self, other: DistillOutcome) {
+        self.stored.extend(other.stored);
+        self.queued.extend(other.queued);
+        self.duplicates += other.duplicates;
+        self.dropped += other.dropped;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stored.is_empty() && self.queued.is_empty()
+    }
+}
+
+/// The extraction prompt for a stretch of conversation.
+pub fn distillation_prompt(messages: &[Message]) -> String {
+    let mut prompt = String::from(
+        "From the conversation below, extract facts that will still be useful in \
+         future sessions on this project:\n\
+         - convention: how the project does things (build, test, style, workflow)\n\
+         - location: where key files, modules or configuration live\n\
+         - decision: choices made and their reasons\n\
+         - preference: how the user wants the assistant to work\n\
+         Leave out the progress of the current task, temporary state, and \
+         anything secret. Rate each fact's confidence from 0 to 1: 1 when the \
+         conversation states it plainly, lower when it is inferred.\n\
+         Reply with only a JSON array of objects with keys \"content\", \
+         \"category\" and \"confidence\", or [] if there is nothing worth keeping.\n\n\
+         Conversation:\n",
+    );
+    prompt.push_str(&crate::summarizer::transcript(messages));
+    prompt
+}
+
+/// Read the extraction model's reply. Entries that do not parse are
+/// skipped, and a reply without a JSON array yields nothing.
+pub fn parse_candidates(reply: &str) -> Vec<CandidateFact> {
+    let (Some(start), Some(end)) = (reply.find('['), reply.rfind(']')) else {
+        return Vec::new();
+    };
+    if end < start {
+        return Vec::new();
+    }
+    let Ok(values) = serde_json::from_str::<Vec<serde_json::Value>>(&reply[start..=end]) else {
+        return Vec::new();
+    };
+    values
+        .into_iter()
+        .filter_map(|v| serde_json::from_value::<CandidateFact>(v).ok())
+        .filter(|c| !c.content.trim().is_empty())
+        .collect()
+}
+
+/// Whether `content` says the same as one of `known`: the same words after
+/// normalization, or bag-of-words vectors with cosine similarity of at least
+/// `threshold`.
+pub fn is_duplicate<'a>(
+    content: &str,
+    known: impl IntoIterator<Item = &'a str>,
+    threshold: f32,
+) -> bool {
+    let service = SimilarityService::new(SimilarityConfig {
+        embedding_threshold: threshold,
+        ..SimilarityConfig::facts()
+    })
+    .with_embedder(Arc::new(SimpleEmbedder::new(SIMILARITY_DIMENSIONS)));
+    known
+        .into_iter()
+        .any(|k| service.compare(content, k).is_some())
+}
+
+/// Sort extracted facts into stored, queued, duplicate and dropped.
+///
+/// `known` is what the workspace already holds; `store_budget` is how many
+/// more facts this session may store. Facts confident enough to store but
+/// over the budget are queued for review instead.
+pub fn triage(
+    candidates: Vec<CandidateFact>,
+    known: &[String],
+    config: &KnowledgeConfig,
+    store_budget: usize,
+    provenance: &Provenance,
+) -> DistillOutcome {
+    let mut outcome = DistillOutcome::default();
+    let mut seen: Vec<String> = known.to_vec();
+    let mut candidates = candidates;
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    for candidate in candidates {
+        let content = candidate.content.trim().to_string();
+        if candidate.confidence < config.review_confidence {
+            outcome.dropped += 1;
+            continue;
+        }
+        if is_duplicate(
+            &content,
+            seen.iter().map(String::as_str),
+            config.duplicate_similarity,
+        ) {
+            outcome.duplicates += 1;
+            continue;
+        }
+        seen.push(content.clone());
+        let fact = LearnedFact {
+            id: Uuid::new_v4(),
+            content,
+            category: candidate.category,
+            confidence: candidate.confidence.clamp(0.0, 1.0),
+            provenance: provenance.clone(),
+        };
+        if fact.confidence >= config.store_confidence && outcome.stored.len() < store_budget {
+            outcome.stored.push(fact);
+        } else {
+            outcome.queued.push(fact);
+        }
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provenance() -> Provenance {
+        Provenance {
+            session_id: Uuid::new_v4(),
+            model: "cheap".into(),
+            trigger: DistillTrigger::SessionClose,
+            learned_at: Utc::now(),
+        }
+    }
+
+    fn candidate(content: &str, confidence: f32) -> CandidateFact {
+        CandidateFact {
+            content: content.into(),
+            category: FactCategory::Convention,
+            confidence,
+        }
+    }
+
+    #[test]
+    fn test_parse_candidates() {
+        let reply = r#"Here you go:
+[
+  {"content": "Tests run with cargo nextest", "category": "convention", "confidence": 0.9},
+  {"content": "Config lives in config/app.toml", "category": "location", "confidence": 0.7},
+  {"content": "no category", "confidence": 0.9}
+]"#;
+        let candidates = parse_candidates(reply);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[1].category, FactCategory::Location);
+        assert!(parse_candidates("nothing to keep").is_empty());
+        assert!(parse_candidates("[]").is_empty());
+    }
+
+    #[test]
+    fn test_triage_thresholds_duplicates_and_budget() {
+        let config = KnowledgeConfig::default();
+        let known = vec!["The user prefers British spelling in docs".to_string()];
+        let outcome = triage(
+            vec![
+                candidate("Tests run with cargo nextest", 0.95),
+                candidate("Run tests with cargo nextest", 0.9),
+                candidate("Migrations live in db/migrations", 0.85),
+                candidate("Docs use British spelling, the user prefers", 0.9),
+                candidate("The API might be versioned", 0.6),
+                candidate("Maybe something", 0.2),
+            ],
+            &known,
+            &config,
+            1,
+            &provenance(),
+        );
+        let stored: Vec<&str> = outcome.stored.iter().map(|f| f.content.as_str()).collect();
+        let queued: Vec<&str> = outcome.queued.iter().map(|f| f.content.as_str()).collect();
+        assert_eq!(stored, ["Tests run with cargo nextest"]);
+        // Over the session's budget, so it waits for review.
+        assert_eq!(
+            queued,
+            [
+                "Migrations live in db/migrations",
+                "The API might be versioned"
+            ]
+        );
+        assert_eq!(outcome.duplicates, 2);
+        assert_eq!(outcome.dropped, 1);
+    }
+
+    #[test]
+    fn test_learned_facts_review_and_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let outcome = triage(
+            vec![
+                candidate("Releases are cut from main", 0.9),
+                candidate("Benchmarks live in benches/", 0.6),
+            ],
+            &[],
+            &KnowledgeConfig::default(),
+            10,
+            &provenance(),
+        );
+        let mut learned = LearnedFacts {
+            facts: outcome.stored,
+            review: outcome.queued,
+        };
+        let pending = learned.review[0].id;
+        assert!(learned.accept(pending).is_some());
+        assert!(learned.reject(pending).is_none());
+        learned.save(dir.path()).unwrap();
+
+        let loaded = LearnedFacts::load(dir.path());
+        assert_eq!(loaded.facts.len(), 2);
+        assert!(loaded.review.is_empty());
+        let fact = loaded.facts[0].to_fact();
+        assert_eq!(fact.source, format!("learned:{}", loaded.facts[0].id));
+        assert!(fact.tags.contains(&"convention".to_string()));
+        assert!(
+            loaded
+                .for_prompt()
+                .contains("- (convention) Benchmarks live in benches/")
+        );
+    }
+}
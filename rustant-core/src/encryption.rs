@@ -1,9 +1,18 @@
 //! Session encryption — AES-256-GCM for encrypting session data at rest.
+//!
+//! Each workspace has its own data key, kept in the configured credential
+//! store under [`session_key_account`] and generated on first use. Sealed
+//! data is tagged with the id of the key that encrypted it, so after a key
+//! rotation starts, files still under the previous key stay readable until
+//! they are re-encrypted.
 
 use aes_gcm::aead::{Aead, KeyInit};
 use aes_gcm::{Aes256Gcm, Nonce};
 use rand::RngCore;
 use rand::rngs::OsRng;
+use std::path::Path;
+
+use crate::credentials::{CredentialError, CredentialStore};
 
 /// Errors that can occur during encryption/decryption.
 #[derive(Debug, thiserror::Error)]
@@ -18,27 +27,62 @@ pub enum EncryptionError {
     DataTooShort,
     #[error("Keyring error: {0}")]
     KeyringError(String),
+    #[error("Data was encrypted with session key {0}, which is not available")]
+    KeyMismatch(String),
+    #[error("Data is encrypted and no session key is available")]
+    NoKey,
 }
 
+/// Prefix of data written by [`SessionEncryptor::seal`], followed by the
+/// 8-byte key id, the nonce and the ciphertext.
+const SEALED_MAGIC: &[u8; 4] = b"RSE1";
+
 /// Encrypts and decrypts session data using AES-256-GCM.
 pub struct SessionEncryptor {
     cipher: Aes256Gcm,
+    key_id: [u8; 8],
+    /// Key being rotated away from, still used to decrypt.
+    previous: Option<Box<SessionEncryptor>>,
 }
 
 impl SessionEncryptor {
     /// Create an encryptor from a raw 32-byte key.
     pub fn from_key(key: &[u8; 32]) -> Self {
+        use sha2::{Digest, Sha256};
+
         let cipher = Aes256Gcm::new_from_slice(key).expect("32-byte key is always valid");
-        Self { cipher }
+        let mut key_id = [0u8; 8];
+        key_id.copy_from_slice(&Sha256::digest(key)[..8]);
+        Self {
+            cipher,
+            key_id,
+            previous: None,
+        }
+    }
+
+    /// Also decrypt data sealed with `previous` (during a key rotation).
+    pub fn with_previous(mut self, previous: SessionEncryptor) -> Self {
+        self.previous = Some(Box::new(previous));
+        self
+    }
+
+    /// Short id of this key, stored with sealed data (not secret).
+    pub fn key_id(&self) -> String {
+        hex_id(&self.key_id)
+    }
+
+    /// Whether a previous key is attached, i.e. a rotation is unfinished.
+    pub fn is_rotating(&self) -> bool {
+        self.previous.is_some()
     }
 
-    /// Create an encryptor from the system keyring.
+    /// Create an encryptor from the configured credential store.
     /// If no key exists, generates and stores a new one.
     pub fn from_keyring() -> Result<Self, EncryptionError> {
-        let store = crate::credentials::KeyringCredentialStore::new();
+        let store = crate::credentials::configured_store();
         let service = "rustant_session_encryption";
 
-        match crate::credentials::CredentialStore::get_key(&store, service) {
+        match store.get_key(service) {
             Ok(key_b64) => {
                 let key_bytes = base64_decode(&key_b64)?;
                 if key_bytes.len() != 32 {
@@ -53,9 +97,9 @@ impl SessionEncryptor {
                 let mut key = [0u8; 32];
                 OsRng.fill_bytes(&mut key);
                 let key_b64 = base64_encode(&key);
-                crate::credentials::CredentialStore::store_key(&store, service, &key_b64).map_err(
-                    |e| EncryptionError::KeyringError(format!("Failed to store key: {}", e)),
-                )?;
+                store.store_key(service, &key_b64).map_err(|e| {
+                    EncryptionError::KeyringError(format!("Failed to store key: {}", e))
+                })?;
                 Ok(Self::from_key(&key))
             }
         }
@@ -94,6 +138,149 @@ impl SessionEncryptor {
             .decrypt(nonce, ciphertext)
             .map_err(|e| EncryptionError::DecryptFailed(e.to_string()))
     }
+
+    /// Encrypt with this key and tag the result with its key id.
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let encrypted = self.encrypt(plaintext)?;
+        let mut sealed = Vec::with_capacity(12 + encrypted.len());
+        sealed.extend_from_slice(SEALED_MAGIC);
+        sealed.extend_from_slice(&self.key_id);
+        sealed.extend_from_slice(&encrypted);
+        Ok(sealed)
+    }
+
+    /// Decrypt data from [`seal`](Self::seal) with whichever attached key
+    /// sealed it. Untagged data from [`encrypt`](Self::encrypt) is tried
+    /// against each key.
+    pub fn unseal(&self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if !is_sealed(data) {
+            return self.decrypt(data).or_else(|e| match &self.previous {
+                Some(previous) => previous.unseal(data),
+                None => Err(e),
+            });
+        }
+        let key_id = &data[4..12];
+        if key_id == self.key_id {
+            return self.decrypt(&data[12..]);
+        }
+        match &self.previous {
+            Some(previous) => previous.unseal(data),
+            None => Err(EncryptionError::KeyMismatch(hex_id(key_id))),
+        }
+    }
+}
+
+/// Whether `data` was written by [`SessionEncryptor::seal`].
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.len() >= 12 && data.starts_with(SEALED_MAGIC)
+}
+
+fn hex_id(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Credential store account holding the session data key of `workspace`.
+pub fn session_key_account(workspace: &Path) -> String {
+    use sha2::{Digest, Sha256};
+
+    let path = workspace
+        .canonicalize()
+        .unwrap_or_else(|_| workspace.to_path_buf());
+    let digest = Sha256::digest(path.to_string_lossy().as_bytes());
+    format!("rustant_session_encryption:{}", hex_id(&digest[..8]))
+}
+
+/// Account holding the key a rotation is moving away from.
+pub fn previous_session_key_account(workspace: &Path) -> String {
+    format!("{}:previous", session_key_account(workspace))
+}
+
+fn read_key(
+    store: &dyn CredentialStore,
+    account: &str,
+) -> Result<Option<[u8; 32]>, EncryptionError> {
+    match store.get_key(account) {
+        Ok(key_b64) => {
+            let key_bytes = base64_decode(&key_b64)?;
+            let key: [u8; 32] = key_bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| EncryptionError::InvalidKeyLength(key_bytes.len()))?;
+            Ok(Some(key))
+        }
+        Err(CredentialError::NotFound { .. }) => Ok(None),
+        Err(e) => Err(EncryptionError::KeyringError(e.to_string())),
+    }
+}
+
+fn generate_key() -> [u8; 32] {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    key
+}
+
+/// Load the session key of `workspace`, with the previous key attached
+/// when a rotation is unfinished. `None` when no key has been stored.
+pub fn load_session_key(
+    store: &dyn CredentialStore,
+    workspace: &Path,
+) -> Result<Option<SessionEncryptor>, EncryptionError> {
+    let Some(key) = read_key(store, &session_key_account(workspace))? else {
+        return Ok(None);
+    };
+    let encryptor = SessionEncryptor::from_key(&key);
+    Ok(Some(
+        match read_key(store, &previous_session_key_account(workspace))? {
+            Some(previous) => encryptor.with_previous(SessionEncryptor::from_key(&previous)),
+            None => encryptor,
+        },
+    ))
+}
+
+/// Generate and store a new session key for `workspace`.
+pub fn create_session_key(
+    store: &dyn CredentialStore,
+    workspace: &Path,
+) -> Result<SessionEncryptor, EncryptionError> {
+    let key = generate_key();
+    store
+        .store_key(&session_key_account(workspace), &base64_encode(&key))
+        .map_err(|e| EncryptionError::KeyringError(format!("Failed to store key: {}", e)))?;
+    Ok(SessionEncryptor::from_key(&key))
+}
+
+/// Start rotating the session key of `workspace`: the current key is kept
+/// as the previous key and a new one takes its place. Resumes an
+/// unfinished rotation instead of starting another.
+pub fn begin_key_rotation(
+    store: &dyn CredentialStore,
+    workspace: &Path,
+) -> Result<SessionEncryptor, EncryptionError> {
+    let account = session_key_account(workspace);
+    let previous_account = previous_session_key_account(workspace);
+    if read_key(store, &previous_account)?.is_some() {
+        return load_session_key(store, workspace)?.ok_or(EncryptionError::NoKey);
+    }
+    let current = read_key(store, &account)?.ok_or(EncryptionError::NoKey)?;
+    store
+        .store_key(&previous_account, &base64_encode(&current))
+        .map_err(|e| EncryptionError::KeyringError(format!("Failed to store key: {}", e)))?;
+    let key = generate_key();
+    store
+        .store_key(&account, &base64_encode(&key))
+        .map_err(|e| EncryptionError::KeyringError(format!("Failed to store key: {}", e)))?;
+    Ok(SessionEncryptor::from_key(&key).with_previous(SessionEncryptor::from_key(&current)))
+}
+
+/// Forget the previous key once everything is re-encrypted.
+pub fn finish_key_rotation(
+    store: &dyn CredentialStore,
+    workspace: &Path,
+) -> Result<(), EncryptionError> {
+    match store.delete_key(&previous_session_key_account(workspace)) {
+        Ok(()) | Err(CredentialError::NotFound { .. }) => Ok(()),
+        Err(e) => Err(EncryptionError::KeyringError(e.to_string())),
+    }
 }
 
 fn base64_encode(data: &[u8]) -> String {
@@ -197,4 +384,54 @@ mod tests {
         let decrypted = encryptor.decrypt(&encrypted).unwrap();
         assert_eq!(decrypted, large_data);
     }
+
+    #[test]
+    fn test_seal_tags_key_and_previous_key_still_opens() {
+        let old = SessionEncryptor::from_key(&test_key());
+        let sealed_old = old.seal(b"before rotation").unwrap();
+        let legacy = old.encrypt(b"untagged").unwrap();
+        assert!(is_sealed(&sealed_old));
+        assert!(!is_sealed(b"{\"messages\": []}"));
+
+        let new = SessionEncryptor::from_key(&[9u8; 32]);
+        assert!(matches!(
+            new.unseal(&sealed_old),
+            Err(EncryptionError::KeyMismatch(id)) if id == old.key_id()
+        ));
+
+        let rotating = new.with_previous(SessionEncryptor::from_key(&test_key()));
+        assert_eq!(rotating.unseal(&sealed_old).unwrap(), b"before rotation");
+        assert_eq!(rotating.unseal(&legacy).unwrap(), b"untagged");
+        let sealed_new = rotating.seal(b"after").unwrap();
+        assert_eq!(
+            &sealed_new[4..12],
+            &SessionEncryptor::from_key(&[9u8; 32]).key_id
+        );
+    }
+
+    #[test]
+    fn test_workspace_key_lifecycle() {
+        let store = crate::credentials::InMemoryCredentialStore::new();
+        let workspace = Path::new("/tmp/rustant-key-test");
+        assert!(load_session_key(&store, workspace).unwrap().is_none());
+
+        let first = create_session_key(&store, workspace).unwrap();
+        let sealed = first.seal(b"transcript").unwrap();
+        let loaded = load_session_key(&store, workspace).unwrap().unwrap();
+        assert_eq!(loaded.key_id(), first.key_id());
+        assert!(!loaded.is_rotating());
+
+        let rotated = begin_key_rotation(&store, workspace).unwrap();
+        assert_ne!(rotated.key_id(), first.key_id());
+        assert_eq!(rotated.unseal(&sealed).unwrap(), b"transcript");
+        // An interrupted rotation resumes with the same keys.
+        let resumed = begin_key_rotation(&store, workspace).unwrap();
+        assert_eq!(resumed.key_id(), rotated.key_id());
+        assert!(resumed.is_rotating());
+
+        finish_key_rotation(&store, workspace).unwrap();
+        let after = load_session_key(&store, workspace).unwrap().unwrap();
+        assert!(!after.is_rotating());
+        assert!(after.unseal(&sealed).is_err());
+    }
 }
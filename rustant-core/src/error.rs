@@ -84,6 +84,16 @@ pub enum LlmError {
 
     #[error("OAuth flow failed: {message}")]
     OAuthFailed { message: String },
+
+    #[error("Provider '{provider}' saturated: waited {waited_ms}ms for a request slot")]
+    Saturated { provider: String, waited_ms: u64 },
+}
+
+impl LlmError {
+    /// Whether an error message (e.g. from a `TaskHandler`) came from [`LlmError::Saturated`].
+    pub fn is_saturation_message(message: &str) -> bool {
+        message.contains("saturated: waited")
+    }
 }
 
 /// Errors from tool registration and execution.
@@ -92,6 +102,10 @@ pub enum ToolError {
     #[error("Tool not found: {name}")]
     NotFound { name: String },
 
+    /// A call to a tool the model made up, with the closest real tools.
+    #[error("Unknown tool '{name}': {hint}")]
+    UnknownTool { name: String, hint: String },
+
     #[error("Tool already registered: {name}")]
     AlreadyRegistered { name: String },
 
@@ -109,6 +123,77 @@ pub enum ToolError {
 
     #[error("Permission denied for tool '{name}': {reason}")]
     PermissionDenied { name: String, reason: String },
+
+    #[error("Tool '{name}' blocked by {violation}")]
+    ContractViolation {
+        name: String,
+        violation: Box<crate::contracts::ContractViolation>,
+    },
+
+    #[error("Tool '{name}' blocked by paranoid mode: {denial}")]
+    CapabilityDenied {
+        name: String,
+        denial: Box<crate::capabilities::CapabilityDenial>,
+    },
+
+    /// A call through an alias whose deprecation schedule has ended.
+    #[error("Tool '{name}' was removed in {version}; use '{replacement}' instead")]
+    Removed {
+        name: String,
+        replacement: String,
+        version: String,
+    },
+
+    /// An execution failure the tool classified itself.
+    #[error("Tool '{name}' execution failed: {message}")]
+    Failed {
+        name: String,
+        message: String,
+        failure: crate::types::ToolFailure,
+    },
+}
+
+impl ToolError {
+    /// An execution failure with a structured kind, retry hint and remediation.
+    pub fn failed(
+        name: impl Into<String>,
+        message: impl Into<String>,
+        failure: crate::types::ToolFailure,
+    ) -> Self {
+        Self::Failed {
+            name: name.into(),
+            message: message.into(),
+            failure,
+        }
+    }
+
+    /// The structured failure behind this error. Errors the tool did not
+    /// classify are classified from their variant or message.
+    pub fn failure(&self) -> crate::types::ToolFailure {
+        use crate::types::{ToolErrorKind, ToolFailure};
+        match self {
+            ToolError::Failed { failure, .. } => failure.clone(),
+            ToolError::NotFound { .. } => ToolFailure::new(ToolErrorKind::NotFound)
+                .with_remediation("Call one of the tools that are available."),
+            ToolError::UnknownTool { .. } => ToolFailure::new(ToolErrorKind::NotFound)
+                .with_remediation("Call one of the tools listed above."),
+            ToolError::Removed { replacement, .. } => ToolFailure::new(ToolErrorKind::NotFound)
+                .with_remediation(format!("Call '{}' instead.", replacement)),
+            ToolError::InvalidArguments { .. } => ToolFailure::new(ToolErrorKind::InvalidArguments),
+            ToolError::Timeout { .. } => ToolFailure::new(ToolErrorKind::Timeout),
+            ToolError::PermissionDenied { .. }
+            | ToolError::ContractViolation { .. }
+            | ToolError::CapabilityDenied { .. } => {
+                ToolFailure::new(ToolErrorKind::PermissionDenied)
+            }
+            ToolError::ExecutionFailed { message, .. } => {
+                ToolFailure::new(ToolErrorKind::classify(message))
+            }
+            ToolError::AlreadyRegistered { .. } | ToolError::Cancelled { .. } => {
+                ToolFailure::new(ToolErrorKind::Internal)
+            }
+        }
+    }
 }
 
 /// Errors from the memory system.
@@ -125,6 +210,9 @@ pub enum MemoryError {
 
     #[error("Failed to load session: {message}")]
     SessionLoadFailed { message: String },
+
+    #[error("Session encryption key unavailable: {message}")]
+    SessionKeyUnavailable { message: String },
 }
 
 /// Errors from the configuration system.
@@ -188,6 +276,12 @@ pub enum AgentError {
 
     #[error("Budget exceeded: {message}")]
     BudgetExceeded { message: String },
+
+    #[error("No interrupted task to continue in this workspace")]
+    NothingToResume,
+
+    #[error("Task aborted: approval for '{tool}' timed out with no decision")]
+    ApprovalTimedOut { tool: String },
 }
 
 /// Errors from the channel system.
@@ -207,6 +301,9 @@ pub enum ChannelError {
 
     #[error("Channel '{name}' rate limited")]
     RateLimited { name: String },
+
+    #[error("Channel '{name}' cannot use room '{room}': end-to-end encryption not yet supported")]
+    EncryptionNotSupported { name: String, room: String },
 }
 
 /// Errors from the node system.
@@ -254,6 +351,9 @@ pub enum WorkflowError {
 
     #[error("Template render error: {message}")]
     TemplateError { message: String },
+
+    #[error("Invalid workflow input '{name}': {message}")]
+    InvalidInput { name: String, message: String },
 }
 
 /// Errors from the browser automation system.
@@ -319,6 +419,12 @@ pub enum SchedulerError {
 
     #[error("Scheduler state persistence error: {message}")]
     PersistenceError { message: String },
+
+    #[error("Job failure notification failed: {message}")]
+    Notification { message: String },
+
+    #[error("Could not understand schedule '{text}': {message}")]
+    UnrecognizedSchedule { text: String, message: String },
 }
 
 /// Errors from the voice and audio system.
@@ -436,6 +542,10 @@ impl UserGuidance for LlmError {
                 "Model '{}' is not supported by this provider.",
                 model
             )),
+            LlmError::Saturated { waited_ms, .. } => Some(format!(
+                "Too many concurrent requests to this provider; gave up after {:.0}s in the queue.",
+                *waited_ms as f64 / 1000.0
+            )),
             _ => None,
         }
     }
@@ -457,6 +567,9 @@ impl UserGuidance for LlmError {
                 "Use /compact to compress conversation history.".into(),
                 "Use /pin to protect important messages before compression.".into(),
             ],
+            LlmError::Saturated { .. } => vec![
+                "Retry once other agents finish, or raise [llm.rate_limits] max_concurrent.".into(),
+            ],
             _ => vec![],
         }
     }
@@ -469,9 +582,14 @@ impl UserGuidance for ToolError {
                 "Tool '{}' is not registered. Use /tools to list available tools.",
                 name
             )),
+            ToolError::UnknownTool { name, .. } => Some(format!(
+                "The model called a tool that does not exist: '{}'.",
+                name
+            )),
             ToolError::InvalidArguments { name, reason } => {
                 Some(format!("Invalid arguments for '{}': {}", name, reason))
             }
+            ToolError::Failed { failure, .. } => Some(failure.remediation_text().to_string()),
             ToolError::ExecutionFailed { name, message } => {
                 // Try to categorize the failure
                 if message.contains("No such file") || message.contains("not found") {
@@ -494,6 +612,10 @@ impl UserGuidance for ToolError {
                 "Permission denied for '{}'. Adjust with /permissions.",
                 name
             )),
+            ToolError::CapabilityDenied { denial, .. } => Some(format!(
+                "Paranoid mode blocked {}. Adjust [safety.paranoid] or change mode with /permissions.",
+                denial.capability
+            )),
             _ => None,
         }
     }
@@ -522,12 +644,23 @@ impl UserGuidance for MemoryError {
                 "Session load failed: {}. Use /sessions to list available sessions.",
                 message
             )),
+            MemoryError::SessionKeyUnavailable { .. } => Some(
+                "Encrypted sessions cannot be read without this workspace's session key.".into(),
+            ),
             _ => None,
         }
     }
 
     fn next_steps(&self) -> Vec<String> {
-        vec![]
+        match self {
+            MemoryError::SessionKeyUnavailable { .. } => vec![
+                "Restore the credential store entry if you have a backup.".into(),
+                "Run `rustant sessions export --metadata-only` to keep titles, dates and token counts."
+                    .into(),
+                "Then move .rustant/sessions aside to start fresh.".into(),
+            ],
+            _ => vec![],
+        }
     }
 }
 
@@ -610,6 +743,10 @@ impl UserGuidance for ChannelError {
                 "Channel '{}' auth failed. Re-run channel setup.",
                 name
             )),
+            ChannelError::EncryptionNotSupported { room, .. } => Some(format!(
+                "Room '{}' is encrypted. Use an unencrypted room for now.",
+                room
+            )),
             _ => None,
         }
     }
@@ -675,6 +812,10 @@ impl UserGuidance for WorkflowError {
                 step
             )),
             WorkflowError::Cancelled => Some("Workflow was cancelled.".into()),
+            WorkflowError::InvalidInput { .. } => Some(
+                "Pass inputs as key=value; `rustant workflow show <name>` lists each input's type."
+                    .into(),
+            ),
             _ => None,
         }
     }
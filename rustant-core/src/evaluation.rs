@@ -0,0 +1,853 @@
+//! Scenario-based evaluation harness for catching agent behavior regressions.
+//!
+//! An evaluation scenario is a YAML file describing a workspace fixture, a
+//! task, tool constraints, budget limits, and assertions over the resulting
+//! workspace state and transcript. Scenarios run against the real agent loop
+//! using either scripted mock responses (deterministic, CI-friendly) or a
+//! live model, and produce a JSON report.
+//!
+//! ```yaml
+//! name: writes-greeting
+//! task: Create hello.txt containing a greeting
+//! files:
+//!   README.md: "# fixture"
+//! allowed_tools: [file_write, file_read]
+//! forbidden_tools: [shell_exec]
+//! budget:
+//!   max_iterations: 5
+//! assertions:
+//!   - file_contains: { path: hello.txt, pattern: hello }
+//!   - tool_not_called: shell_exec
+//! mock_responses:
+//!   - tool_call: { name: file_write, arguments: { path: hello.txt, content: hello } }
+//!   - text: Done
+//! ```
+
+use crate::brain::MockLlmProvider;
+use crate::types::{Content, CostEstimate, Message, Role, TokenUsage};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Errors that can occur while loading or preparing evaluation scenarios.
+#[derive(Debug, thiserror::Error)]
+pub enum EvalError {
+    #[error("failed to read scenario {path}: {message}")]
+    Read { path: PathBuf, message: String },
+    #[error("failed to parse scenario {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+    #[error("invalid scenario '{name}': {message}")]
+    Invalid { name: String, message: String },
+    #[error("workspace preparation failed for '{name}': {message}")]
+    Workspace { name: String, message: String },
+}
+
+/// A single evaluation scenario parsed from YAML.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalScenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Fixture directory copied into the scenario workspace, relative to the scenario file.
+    #[serde(default)]
+    pub fixture: Option<PathBuf>,
+    /// Inline files written into the workspace after the fixture is copied.
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+    /// The task text given to the agent.
+    pub task: String,
+    /// If non-empty, only these tools are registered with the agent.
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    /// Tools that are never registered and must not appear in the transcript.
+    #[serde(default)]
+    pub forbidden_tools: Vec<String>,
+    #[serde(default)]
+    pub budget: EvalBudget,
+    #[serde(default, with = "serde_yaml::with::singleton_map_recursive")]
+    pub assertions: Vec<Assertion>,
+    /// Scripted provider responses used when running without a live model.
+    #[serde(default, with = "serde_yaml::with::singleton_map_recursive")]
+    pub mock_responses: Vec<MockStep>,
+}
+
+/// Resource limits applied to a scenario run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvalBudget {
+    #[serde(default)]
+    pub max_iterations: Option<usize>,
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// One scripted response from the mock provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MockStep {
+    Text(String),
+    ToolCall {
+        name: String,
+        #[serde(default = "empty_object")]
+        arguments: serde_json::Value,
+    },
+}
+
+fn empty_object() -> serde_json::Value {
+    serde_json::json!({})
+}
+
+/// An assertion over workspace state or transcript content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Assertion {
+    FileExists(String),
+    FileAbsent(String),
+    FileContains { path: String, pattern: String },
+    FileNotContains { path: String, pattern: String },
+    ResponseContains(String),
+    TranscriptContains(String),
+    TranscriptNotContains(String),
+    ToolCalled(String),
+    ToolNotCalled(String),
+}
+
+impl std::fmt::Display for Assertion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Assertion::FileExists(p) => write!(f, "file_exists({})", p),
+            Assertion::FileAbsent(p) => write!(f, "file_absent({})", p),
+            Assertion::FileContains { path, pattern } => {
+                write!(f, "file_contains({}, {:?})", path, pattern)
+            }
+            Assertion::FileNotContains { path, pattern } => {
+                write!(f, "file_not_contains({}, {:?})", path, pattern)
+            }
+            Assertion::ResponseContains(s) => write!(f, "response_contains({:?})", s),
+            Assertion::TranscriptContains(s) => write!(f, "transcript_contains({:?})", s),
+            Assertion::TranscriptNotContains(s) => write!(f, "transcript_not_contains({:?})", s),
+            Assertion::ToolCalled(t) => write!(f, "tool_called({})", t),
+            Assertion::ToolNotCalled(t) => write!(f, "tool_not_called({})", t),
+        }
+    }
+}
+
+impl EvalScenario {
+    /// Parse a scenario from YAML text.
+    pub fn from_yaml(yaml: &str, path: &Path) -> Result<Self, EvalError> {
+        let scenario: EvalScenario = serde_yaml::from_str(yaml).map_err(|e| EvalError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Check the scenario for structural problems.
+    pub fn validate(&self) -> Result<(), EvalError> {
+        let invalid = |message: String| EvalError::Invalid {
+            name: self.name.clone(),
+            message,
+        };
+        if self.name.trim().is_empty() {
+            return Err(invalid("name must not be empty".into()));
+        }
+        if self.task.trim().is_empty() {
+            return Err(invalid("task must not be empty".into()));
+        }
+        if let Some(tool) = self
+            .forbidden_tools
+            .iter()
+            .find(|t| self.allowed_tools.contains(t))
+        {
+            return Err(invalid(format!(
+                "tool '{}' is both allowed and forbidden",
+                tool
+            )));
+        }
+        for path in self.files.keys() {
+            if !is_safe_relative(Path::new(path)) {
+                return Err(invalid(format!(
+                    "inline file path '{}' escapes workspace",
+                    path
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a tool should be registered with the agent for this scenario.
+    pub fn tool_permitted(&self, tool: &str) -> bool {
+        if self.forbidden_tools.iter().any(|t| t == tool) {
+            return false;
+        }
+        self.allowed_tools.is_empty() || self.allowed_tools.iter().any(|t| t == tool)
+    }
+
+    /// Build a mock provider that replays this scenario's scripted responses.
+    ///
+    /// Returns `None` when the scenario has no scripted responses.
+    pub fn mock_provider(&self) -> Option<MockLlmProvider> {
+        if self.mock_responses.is_empty() {
+            return None;
+        }
+        let provider = MockLlmProvider::new();
+        for step in &self.mock_responses {
+            let response = match step {
+                MockStep::Text(text) => MockLlmProvider::text_response(text),
+                MockStep::ToolCall { name, arguments } => {
+                    MockLlmProvider::tool_call_response(name, arguments.clone())
+                }
+            };
+            provider.queue_response(response);
+        }
+        Some(provider)
+    }
+
+    /// File-system friendly identifier derived from the scenario name.
+    pub fn slug(&self) -> String {
+        let slug: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        slug.trim_matches('-').to_string()
+    }
+}
+
+/// Returns true when `path` is relative and never climbs above its root.
+fn is_safe_relative(path: &Path) -> bool {
+    !path.as_os_str().is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Load every `*.yaml` / `*.yml` scenario in `dir`, sorted by file name.
+///
+/// Each entry is paired with the directory containing the scenario so that
+/// relative fixture paths can be resolved.
+pub fn load_scenarios(dir: &Path) -> Result<Vec<(PathBuf, EvalScenario)>, EvalError> {
+    let read_err = |path: &Path, e: std::io::Error| EvalError::Read {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    };
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| read_err(dir, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.is_file()
+                && matches!(
+                    p.extension().and_then(|e| e.to_str()),
+                    Some("yaml") | Some("yml")
+                )
+        })
+        .collect();
+    paths.sort();
+
+    let mut scenarios = Vec::with_capacity(paths.len());
+    let mut seen = std::collections::HashSet::new();
+    for path in paths {
+        let yaml = std::fs::read_to_string(&path).map_err(|e| read_err(&path, e))?;
+        let scenario = EvalScenario::from_yaml(&yaml, &path)?;
+        if !seen.insert(scenario.slug()) {
+            return Err(EvalError::Invalid {
+                name: scenario.name,
+                message: "duplicate scenario name".into(),
+            });
+        }
+        let base = path.parent().unwrap_or(dir).to_path_buf();
+        scenarios.push((base, scenario));
+    }
+    Ok(scenarios)
+}
+
+/// Create a fresh, deterministic workspace for a scenario under `root`.
+///
+/// The workspace path is always `root/<slug>`; any previous contents are
+/// removed so repeated runs start from identical state.
+pub fn prepare_workspace(
+    scenario: &EvalScenario,
+    scenario_dir: &Path,
+    root: &Path,
+) -> Result<PathBuf, EvalError> {
+    let ws_err = |message: String| EvalError::Workspace {
+        name: scenario.name.clone(),
+        message,
+    };
+    let workspace = root.join(scenario.slug());
+    if workspace.exists() {
+        std::fs::remove_dir_all(&workspace).map_err(|e| ws_err(e.to_string()))?;
+    }
+    std::fs::create_dir_all(&workspace).map_err(|e| ws_err(e.to_string()))?;
+
+    if let Some(fixture) = &scenario.fixture {
+        let source = scenario_dir.join(fixture);
+        if !source.is_dir() {
+            return Err(ws_err(format!(
+                "fixture directory not found: {}",
+                source.display()
+            )));
+        }
+        copy_dir(&source, &workspace).map_err(|e| ws_err(e.to_string()))?;
+    }
+
+    for (rel, content) in &scenario.files {
+        let target = workspace.join(rel);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| ws_err(e.to_string()))?;
+        }
+        std::fs::write(&target, content).map_err(|e| ws_err(e.to_string()))?;
+    }
+    Ok(workspace)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    for entry in walkdir::WalkDir::new(from).follow_links(false) {
+        let entry = entry.map_err(std::io::Error::other)?;
+        let rel = entry
+            .path()
+            .strip_prefix(from)
+            .map_err(std::io::Error::other)?;
+        let target = to.join(rel);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// One message in a recorded scenario transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    pub role: Role,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<serde_json::Value>,
+}
+
+/// Everything observed while running a scenario.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScenarioTranscript {
+    pub task: String,
+    pub response: String,
+    pub entries: Vec<TranscriptEntry>,
+    /// Names of every tool the model requested, in order.
+    pub tool_calls: Vec<String>,
+    pub iterations: usize,
+    pub usage: TokenUsage,
+    pub cost: CostEstimate,
+    /// Agent error, if the run did not complete successfully.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ScenarioTranscript {
+    /// Build a transcript from the agent's conversation messages.
+    pub fn from_messages(task: &str, messages: &[Message]) -> Self {
+        let mut transcript = Self {
+            task: task.to_string(),
+            ..Default::default()
+        };
+        for message in messages {
+            transcript.push_content(message.role, &message.content);
+        }
+        transcript
+    }
+
+    fn push_content(&mut self, role: Role, content: &Content) {
+        match content {
+            Content::Text { text } => self.entries.push(TranscriptEntry {
+                role,
+                text: Some(text.clone()),
+                tool: None,
+                arguments: None,
+            }),
+            Content::ToolCall {
+                name, arguments, ..
+            } => {
+                self.tool_calls.push(name.clone());
+                self.entries.push(TranscriptEntry {
+                    role,
+                    text: None,
+                    tool: Some(name.clone()),
+                    arguments: Some(arguments.clone()),
+                });
+            }
+            Content::ToolResult { output, .. } => self.entries.push(TranscriptEntry {
+                role,
+                text: Some(output.clone()),
+                tool: None,
+                arguments: None,
+            }),
+            Content::MultiPart { parts } => {
+                for part in parts {
+                    self.push_content(role, part);
+                }
+            }
+            Content::Attachment { handle, .. } => self.entries.push(TranscriptEntry {
+                role,
+                text: Some(handle.reference()),
+                tool: None,
+                arguments: None,
+            }),
+        }
+    }
+
+    /// All textual content of the transcript joined for substring assertions.
+    pub fn full_text(&self) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            if let Some(text) = &entry.text {
+                out.push_str(text);
+                out.push('\n');
+            }
+            if let Some(args) = &entry.arguments {
+                out.push_str(&args.to_string());
+                out.push('\n');
+            }
+        }
+        out.push_str(&self.response);
+        out
+    }
+}
+
+/// Result of evaluating a single check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckOutcome {
+    pub check: String,
+    pub passed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl CheckOutcome {
+    pub fn pass(check: impl Into<String>) -> Self {
+        Self {
+            check: check.into(),
+            passed: true,
+            detail: None,
+        }
+    }
+
+    pub fn fail(check: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            check: check.into(),
+            passed: false,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+/// Evaluate all checks for a scenario: agent completion, forbidden tools,
+/// budget limits, and every declared assertion.
+pub fn evaluate(
+    scenario: &EvalScenario,
+    workspace: &Path,
+    transcript: &ScenarioTranscript,
+) -> Vec<CheckOutcome> {
+    let mut outcomes = Vec::new();
+
+    outcomes.push(match &transcript.error {
+        None => CheckOutcome::pass("completed"),
+        Some(e) => CheckOutcome::fail("completed", e.clone()),
+    });
+
+    for tool in &scenario.forbidden_tools {
+        let check = format!("forbidden_tool({})", tool);
+        outcomes.push(if transcript.tool_calls.contains(tool) {
+            CheckOutcome::fail(check, "forbidden tool was requested by the model")
+        } else {
+            CheckOutcome::pass(check)
+        });
+    }
+    if !scenario.allowed_tools.is_empty() {
+        let unexpected: Vec<&String> = transcript
+            .tool_calls
+            .iter()
+            .filter(|t| {
+                *t != "ask_user"
+                    && *t != crate::pins::PIN_TOOL
+                    && !scenario.allowed_tools.contains(t)
+            })
+            .collect();
+        outcomes.push(if unexpected.is_empty() {
+            CheckOutcome::pass("allowed_tools")
+        } else {
+            CheckOutcome::fail(
+                "allowed_tools",
+                format!(
+                    "tools outside the allow-list were requested: {:?}",
+                    unexpected
+                ),
+            )
+        });
+    }
+
+    if let Some(max) = scenario.budget.max_tokens {
+        let used = transcript.usage.total();
+        outcomes.push(if used <= max {
+            CheckOutcome::pass("max_tokens")
+        } else {
+            CheckOutcome::fail(
+                "max_tokens",
+                format!("used {} tokens (limit {})", used, max),
+            )
+        });
+    }
+    if let Some(max) = scenario.budget.max_cost_usd {
+        let cost = transcript.cost.total();
+        outcomes.push(if cost <= max {
+            CheckOutcome::pass("max_cost_usd")
+        } else {
+            CheckOutcome::fail(
+                "max_cost_usd",
+                format!("cost ${:.4} (limit ${:.4})", cost, max),
+            )
+        });
+    }
+
+    let full_text = transcript.full_text();
+    for assertion in &scenario.assertions {
+        outcomes.push(check_assertion(
+            assertion, workspace, transcript, &full_text,
+        ));
+    }
+    outcomes
+}
+
+fn check_assertion(
+    assertion: &Assertion,
+    workspace: &Path,
+    transcript: &ScenarioTranscript,
+    full_text: &str,
+) -> CheckOutcome {
+    let name = assertion.to_string();
+    let read = |path: &str| -> Result<String, String> {
+        if !is_safe_relative(Path::new(path)) {
+            return Err(format!("path '{}' escapes workspace", path));
+        }
+        std::fs::read_to_string(workspace.join(path)).map_err(|e| e.to_string())
+    };
+    let verdict = |ok: bool, detail: &str| {
+        if ok {
+            CheckOutcome::pass(name.clone())
+        } else {
+            CheckOutcome::fail(name.clone(), detail)
+        }
+    };
+
+    match assertion {
+        Assertion::FileExists(path) => verdict(
+            is_safe_relative(Path::new(path)) && workspace.join(path).exists(),
+            "file does not exist",
+        ),
+        Assertion::FileAbsent(path) => verdict(
+            is_safe_relative(Path::new(path)) && !workspace.join(path).exists(),
+            "file exists",
+        ),
+        Assertion::FileContains { path, pattern } => match read(path) {
+            Ok(content) => verdict(content.contains(pattern.as_str()), "pattern not found"),
+            Err(e) => CheckOutcome::fail(name, e),
+        },
+        Assertion::FileNotContains { path, pattern } => match read(path) {
+            Ok(content) => verdict(!content.contains(pattern.as_str()), "pattern found"),
+            Err(e) => CheckOutcome::fail(name, e),
+        },
+        Assertion::ResponseContains(s) => verdict(
+            transcript.response.contains(s.as_str()),
+            "final response does not contain text",
+        ),
+        Assertion::TranscriptContains(s) => verdict(
+            full_text.contains(s.as_str()),
+            "transcript does not contain text",
+        ),
+        Assertion::TranscriptNotContains(s) => {
+            verdict(!full_text.contains(s.as_str()), "transcript contains text")
+        }
+        Assertion::ToolCalled(t) => verdict(
+            transcript.tool_calls.iter().any(|c| c == t),
+            "tool was never called",
+        ),
+        Assertion::ToolNotCalled(t) => verdict(
+            !transcript.tool_calls.iter().any(|c| c == t),
+            "tool was called",
+        ),
+    }
+}
+
+/// Outcome of a single scenario run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioResult {
+    pub name: String,
+    pub passed: bool,
+    pub duration_ms: u64,
+    pub checks: Vec<CheckOutcome>,
+    pub workspace: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript_path: Option<PathBuf>,
+}
+
+impl ScenarioResult {
+    /// Build a result from evaluated checks; passes only if every check passed.
+    pub fn new(name: impl Into<String>, workspace: PathBuf, checks: Vec<CheckOutcome>) -> Self {
+        let passed = !checks.is_empty() && checks.iter().all(|c| c.passed);
+        Self {
+            name: name.into(),
+            passed,
+            duration_ms: 0,
+            checks,
+            workspace,
+            transcript_path: None,
+        }
+    }
+
+    /// Checks that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &CheckOutcome> {
+        self.checks.iter().filter(|c| !c.passed)
+    }
+}
+
+/// Whether scenarios ran against scripted responses or a live model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvalMode {
+    Mock,
+    Live,
+}
+
+/// Aggregate report for an evaluation run, serialized for CI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalReport {
+    pub started_at: DateTime<Utc>,
+    pub mode: EvalMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub results: Vec<ScenarioResult>,
+}
+
+impl EvalReport {
+    /// Build a report from scenario results (sorted by name for stable output).
+    pub fn new(
+        started_at: DateTime<Utc>,
+        mode: EvalMode,
+        model: Option<String>,
+        mut results: Vec<ScenarioResult>,
+    ) -> Self {
+        results.sort_by(|a, b| a.name.cmp(&b.name));
+        let passed = results.iter().filter(|r| r.passed).count();
+        Self {
+            started_at,
+            mode,
+            model,
+            total: results.len(),
+            passed,
+            failed: results.len() - passed,
+            results,
+        }
+    }
+
+    /// Whether every scenario passed.
+    pub fn all_passed(&self) -> bool {
+        self.failed == 0
+    }
+
+    /// Human-readable pass/fail summary.
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        for result in &self.results {
+            out.push_str(&format!(
+                "  [{}] {} ({}ms)\n",
+                if result.passed { "PASS" } else { "FAIL" },
+                result.name,
+                result.duration_ms
+            ));
+            for failure in result.failures() {
+                out.push_str(&format!(
+                    "      - {}: {}\n",
+                    failure.check,
+                    failure.detail.as_deref().unwrap_or("failed")
+                ));
+            }
+        }
+        out.push_str(&format!(
+            "\n  {} passed, {} failed, {} total",
+            self.passed, self.failed, self.total
+        ));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SCENARIO: &str = r##"
+name: Writes Greeting
+task: Create hello.txt containing a greeting
+files:
+  README.md: "# fixture"
+  src/lib.rs: "pub fn a() {}"
+allowed_tools: [file_write, file_read]
+forbidden_tools: [shell_exec]
+budget:
+  max_iterations: 5
+  max_tokens: 1000
+assertions:
+  - file_contains: { path: hello.txt, pattern: hello }
+  - tool_not_called: shell_exec
+  - response_contains: Done
+mock_responses:
+  - tool_call: { name: file_write, arguments: { path: hello.txt, content: hello } }
+  - text: Done
+"##;
+
+    fn parse() -> EvalScenario {
+        EvalScenario::from_yaml(SCENARIO, Path::new("s.yaml")).unwrap()
+    }
+
+    #[test]
+    fn test_parse_scenario() {
+        let s = parse();
+        assert_eq!(s.slug(), "writes-greeting");
+        assert_eq!(s.files.len(), 2);
+        assert_eq!(s.assertions.len(), 3);
+        assert_eq!(s.mock_responses.len(), 2);
+        assert_eq!(s.budget.max_iterations, Some(5));
+        assert!(s.tool_permitted("file_write"));
+        assert!(!s.tool_permitted("shell_exec"));
+        assert!(!s.tool_permitted("git_commit"));
+        assert!(s.mock_provider().is_some());
+    }
+
+    #[test]
+    fn test_validate_rejects_conflicts_and_traversal() {
+        let mut s = parse();
+        s.allowed_tools.push("shell_exec".into());
+        assert!(s.validate().is_err());
+
+        let mut s = parse();
+        s.files.insert("../escape.txt".into(), "x".into());
+        assert!(s.validate().is_err());
+    }
+
+    #[test]
+    fn test_prepare_workspace_is_deterministic() {
+        let dir = TempDir::new().unwrap();
+        let fixture = dir.path().join("fixture");
+        std::fs::create_dir_all(fixture.join("nested")).unwrap();
+        std::fs::write(fixture.join("nested/data.txt"), "fixture data").unwrap();
+
+        let mut s = parse();
+        s.fixture = Some(PathBuf::from("fixture"));
+        let root = dir.path().join("runs");
+
+        let ws = prepare_workspace(&s, dir.path(), &root).unwrap();
+        assert_eq!(ws, root.join("writes-greeting"));
+        assert!(ws.join("nested/data.txt").exists());
+        assert!(ws.join("src/lib.rs").exists());
+
+        // Stray files from a previous run are removed.
+        std::fs::write(ws.join("stray.txt"), "x").unwrap();
+        let ws2 = prepare_workspace(&s, dir.path(), &root).unwrap();
+        assert_eq!(ws, ws2);
+        assert!(!ws2.join("stray.txt").exists());
+    }
+
+    #[test]
+    fn test_evaluate_passing_and_failing() {
+        let dir = TempDir::new().unwrap();
+        let s = parse();
+        std::fs::write(dir.path().join("hello.txt"), "hello world").unwrap();
+
+        let messages = vec![
+            Message::user("Create hello.txt"),
+            Message::new(
+                Role::Assistant,
+                Content::tool_call("c1", "file_write", serde_json::json!({"path": "hello.txt"})),
+            ),
+            Message::tool_result("c1", "ok", false),
+            Message::assistant("Done"),
+        ];
+        let mut transcript = ScenarioTranscript::from_messages(&s.task, &messages);
+        transcript.response = "Done".into();
+        assert_eq!(transcript.tool_calls, vec!["file_write"]);
+
+        let result = ScenarioResult::new(
+            &s.name,
+            dir.path().into(),
+            evaluate(&s, dir.path(), &transcript),
+        );
+        assert!(result.passed, "{:?}", result.checks);
+
+        transcript.tool_calls.push("shell_exec".into());
+        transcript.usage.input_tokens = 5000;
+        let result = ScenarioResult::new(
+            &s.name,
+            dir.path().into(),
+            evaluate(&s, dir.path(), &transcript),
+        );
+        assert!(!result.passed);
+        let failed: Vec<&str> = result.failures().map(|c| c.check.as_str()).collect();
+        assert!(failed.contains(&"forbidden_tool(shell_exec)"));
+        assert!(failed.contains(&"allowed_tools"));
+        assert!(failed.contains(&"max_tokens"));
+    }
+
+    #[test]
+    fn test_load_scenarios_sorted_and_unique() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("b.yaml"), "name: b\ntask: do b\n").unwrap();
+        std::fs::write(dir.path().join("a.yml"), "name: a\ntask: do a\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+        let loaded = load_scenarios(dir.path()).unwrap();
+        let names: Vec<&str> = loaded.iter().map(|(_, s)| s.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+
+        std::fs::write(dir.path().join("c.yaml"), "name: A\ntask: dup\n").unwrap();
+        assert!(load_scenarios(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_report_summary() {
+        let ok = ScenarioResult::new(
+            "a",
+            PathBuf::from("a"),
+            vec![CheckOutcome::pass("completed")],
+        );
+        let bad = ScenarioResult::new(
+            "b",
+            PathBuf::from("b"),
+            vec![CheckOutcome::fail("completed", "boom")],
+        );
+        let report = EvalReport::new(Utc::now(), EvalMode::Mock, None, vec![bad, ok]);
+        assert_eq!(report.total, 2);
+        assert_eq!(report.passed, 1);
+        assert!(!report.all_passed());
+        assert_eq!(report.results[0].name, "a");
+        let summary = report.summary();
+        assert!(summary.contains("[FAIL] b"));
+        assert!(summary.contains("completed: boom"));
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["mode"], "mock");
+    }
+}
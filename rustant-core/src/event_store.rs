@@ -0,0 +1,1587 @@
+//! Local event store — structured agent-loop events in SQLite.
+//!
+//! With `[event_store] enabled = true`, task starts and finishes, tool
+//! calls, LLM requests, approval decisions and safety blocks are written to
+//! `events.db` in the Rustant data directory, one database for every
+//! workspace. `rustant stats` runs canned reports over it and ad hoc
+//! read-only SQL.
+//!
+//! LLM usage is also rolled up per day, workspace and model into
+//! `cost_rollups`, which retention leaves alone, so monthly cost reports
+//! and the `[budget] monthly_soft_cap_usd` check outlive the raw events.
+//!
+//! Recording never waits on the database: [`record`] puts the event on a
+//! bounded queue that a background thread drains in batches, one
+//! transaction per batch. When the queue is full the event is dropped. The
+//! schema is versioned: [`MIGRATIONS`] are applied in order when the store
+//! is opened, so databases written by older releases are upgraded in place.
+
+use crate::canvas::{
+    AnnotationAxis, AxisSpec, AxisValue, ChartAnnotation, ChartDataset, ChartSpec,
+};
+use crate::safety::AuditEvent;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+use uuid::Uuid;
+
+/// File name of the store inside the data directory.
+const DB_FILE: &str = "events.db";
+
+/// Characters of a tool call's detail (command, path, URL) kept.
+const MAX_DETAIL_CHARS: usize = 200;
+
+/// Rows returned by an ad hoc query.
+const MAX_QUERY_ROWS: usize = 1000;
+
+/// Timestamp format of the `ts` columns: UTC, sortable, and understood by
+/// SQLite's date functions.
+const TS_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Schema migrations, applied in order. Entry `i` takes the database from
+/// version `i` to `i + 1`. Append new entries; never edit released ones.
+pub const MIGRATIONS: &[&str] = &[
+    r#"
+CREATE TABLE task_events (
+    id INTEGER PRIMARY KEY,
+    ts TEXT NOT NULL,
+    workspace TEXT NOT NULL,
+    session_id TEXT,
+    task_id TEXT NOT NULL,
+    phase TEXT NOT NULL,
+    route TEXT,
+    success INTEGER,
+    duration_ms INTEGER,
+    iterations INTEGER,
+    input_tokens INTEGER,
+    output_tokens INTEGER,
+    cost_usd REAL
+);
+CREATE TABLE tool_calls (
+    id INTEGER PRIMARY KEY,
+    ts TEXT NOT NULL,
+    workspace TEXT NOT NULL,
+    session_id TEXT,
+    task_id TEXT,
+    tool TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    success INTEGER NOT NULL,
+    error_kind TEXT,
+    detail TEXT
+);
+CREATE TABLE llm_requests (
+    id INTEGER PRIMARY KEY,
+    ts TEXT NOT NULL,
+    workspace TEXT NOT NULL,
+    model TEXT NOT NULL,
+    success INTEGER NOT NULL,
+    latency_ms INTEGER NOT NULL,
+    input_tokens INTEGER NOT NULL,
+    output_tokens INTEGER NOT NULL,
+    cost_usd REAL NOT NULL
+);
+CREATE TABLE approvals (
+    id INTEGER PRIMARY KEY,
+    ts TEXT NOT NULL,
+    workspace TEXT NOT NULL,
+    session_id TEXT,
+    tool TEXT NOT NULL,
+    approved INTEGER NOT NULL
+);
+CREATE TABLE safety_blocks (
+    id INTEGER PRIMARY KEY,
+    ts TEXT NOT NULL,
+    workspace TEXT NOT NULL,
+    session_id TEXT,
+    tool TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    reason TEXT NOT NULL
+);
+CREATE INDEX task_events_ts ON task_events (ts);
+CREATE INDEX tool_calls_ts ON tool_calls (ts);
+CREATE INDEX llm_requests_ts ON llm_requests (ts);
+CREATE INDEX approvals_ts ON approvals (ts);
+CREATE INDEX safety_blocks_ts ON safety_blocks (ts);
+"#,
+    r#"
+ALTER TABLE llm_requests ADD COLUMN priced INTEGER NOT NULL DEFAULT 1;
+CREATE TABLE cost_rollups (
+    day TEXT NOT NULL,
+    workspace TEXT NOT NULL,
+    model TEXT NOT NULL,
+    requests INTEGER NOT NULL,
+    input_tokens INTEGER NOT NULL,
+    output_tokens INTEGER NOT NULL,
+    cost_usd REAL NOT NULL,
+    unpriced_requests INTEGER NOT NULL,
+    PRIMARY KEY (day, workspace, model)
+);
+INSERT INTO cost_rollups
+SELECT substr(ts, 1, 10), workspace, model, COUNT(*), SUM(input_tokens),
+    SUM(output_tokens), SUM(cost_usd), 0
+FROM llm_requests GROUP BY substr(ts, 1, 10), workspace, model;
+"#,
+];
+
+/// Tables holding events, each with `ts` and `workspace` columns.
+/// `cost_rollups` is not one of them: retention keeps it.
+const EVENT_TABLES: &[&str] = &[
+    "task_events",
+    "tool_calls",
+    "llm_requests",
+    "approvals",
+    "safety_blocks",
+];
+
+/// Configuration for the local event store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventStoreConfig {
+    /// Whether the agent loop writes events.
+    pub enabled: bool,
+    /// Database file; defaults to `events.db` in the Rustant data directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Days events are kept; 0 keeps them forever.
+    pub retention_days: u32,
+    /// Events written per transaction at most.
+    pub batch_size: usize,
+    /// Longest time an event waits before its batch is written.
+    pub flush_interval_ms: u64,
+    /// Events queued for writing; further events are dropped when full.
+    pub queue_capacity: usize,
+}
+
+impl Default for EventStoreConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            retention_days: 90,
+            batch_size: 100,
+            flush_interval_ms: 1000,
+            queue_capacity: 4096,
+        }
+    }
+}
+
+impl EventStoreConfig {
+    /// The configured database path, or the default one.
+    pub fn resolved_path(&self) -> Option<PathBuf> {
+        self.path.clone().or_else(default_path)
+    }
+}
+
+/// `events.db` in the Rustant data directory.
+pub fn default_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("dev", "rustant", "rustant").map(|d| d.data_dir().join(DB_FILE))
+}
+
+/// Errors from the event store.
+#[derive(Debug, thiserror::Error)]
+pub enum EventStoreError {
+    #[error("event store database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    #[error("event store I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("could not determine the rustant data directory; set [event_store] path")]
+    NoDataDir,
+
+    #[error("no event store at {}; enable [event_store] and run a task first", .0.display())]
+    Missing(PathBuf),
+}
+
+/// Something the agent loop did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    TaskStarted {
+        task_id: Uuid,
+        session_id: Option<Uuid>,
+    },
+    TaskFinished {
+        task_id: Uuid,
+        session_id: Option<Uuid>,
+        /// `fast_path`, `fallback` or `full`.
+        route: String,
+        success: bool,
+        duration_ms: u64,
+        iterations: usize,
+        input_tokens: u64,
+        output_tokens: u64,
+        cost_usd: f64,
+    },
+    ToolCall {
+        task_id: Option<Uuid>,
+        session_id: Option<Uuid>,
+        tool: String,
+        duration_ms: u64,
+        success: bool,
+        error_kind: Option<String>,
+        /// The call's main argument, from [`call_detail`].
+        detail: Option<String>,
+    },
+    LlmRequest {
+        model: String,
+        success: bool,
+        latency_ms: u64,
+        input_tokens: u64,
+        output_tokens: u64,
+        /// `None` when the model's pricing is unknown.
+        cost_usd: Option<f64>,
+    },
+    Approval {
+        session_id: Option<Uuid>,
+        tool: String,
+        approved: bool,
+    },
+    /// A call refused by the safety layer: permission denial, contract
+    /// violation, paranoid-mode capability block or agent limit.
+    SafetyBlock {
+        session_id: Option<Uuid>,
+        tool: String,
+        kind: String,
+        reason: String,
+    },
+}
+
+/// An event with when and in which workspace it happened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventRecord {
+    pub at: DateTime<Utc>,
+    pub workspace: String,
+    pub event: Event,
+}
+
+/// The argument that identifies what a tool call acted on — its command,
+/// path, URL or query — truncated.
+pub fn call_detail(arguments: &serde_json::Value) -> Option<String> {
+    ["command", "path", "url", "query", "action"]
+        .iter()
+        .find_map(|key| arguments.get(key).and_then(|v| v.as_str()))
+        .map(|value| match value.char_indices().nth(MAX_DETAIL_CHARS) {
+            Some((end, _)) => format!("{}…", &value[..end]),
+            None => value.to_string(),
+        })
+}
+
+// ---------------------------------------------------------------------------
+// Store
+// ---------------------------------------------------------------------------
+
+/// Calls of one tool and how many failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolStats {
+    pub tool: String,
+    pub calls: u64,
+    pub failures: u64,
+    pub avg_duration_ms: f64,
+}
+
+impl ToolStats {
+    pub fn failure_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.calls as f64
+        }
+    }
+}
+
+/// Failed calls of a tool with the same detail, e.g. the same command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureDetail {
+    pub detail: String,
+    pub failures: u64,
+    pub error_kind: Option<String>,
+    pub last_seen: String,
+}
+
+/// LLM usage of one model on one day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyCost {
+    pub day: String,
+    pub model: String,
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Cost of the priced requests.
+    pub cost_usd: f64,
+    /// Requests to models with unknown pricing, not included in `cost_usd`.
+    pub unpriced_requests: u64,
+}
+
+/// LLM usage of one model in one workspace over a month.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthlyCost {
+    pub workspace: String,
+    pub model: String,
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Cost of the priced requests.
+    pub cost_usd: f64,
+    /// Requests to models with unknown pricing, not included in `cost_usd`.
+    pub unpriced_requests: u64,
+}
+
+/// Render a cost whose requests may be partly unpriced: `$1.2345`,
+/// `≥$1.2345` when some requests are unpriced, `unknown` when all are.
+pub fn format_cost(cost_usd: f64, requests: u64, unpriced_requests: u64) -> String {
+    if requests > 0 && unpriced_requests >= requests {
+        "unknown".to_string()
+    } else if unpriced_requests > 0 {
+        format!("≥${:.4}", cost_usd)
+    } else {
+        format!("${:.4}", cost_usd)
+    }
+}
+
+/// Activity in one workspace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceActivity {
+    pub workspace: String,
+    pub tasks: u64,
+    pub tool_calls: u64,
+    pub cost_usd: f64,
+}
+
+/// Approval decisions for one tool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApprovalStats {
+    pub tool: String,
+    pub approved: u64,
+    pub denied: u64,
+}
+
+impl ApprovalStats {
+    pub fn deny_rate(&self) -> f64 {
+        let total = self.approved + self.denied;
+        if total == 0 {
+            0.0
+        } else {
+            self.denied as f64 / total as f64
+        }
+    }
+}
+
+/// Result of an ad hoc query, with every value rendered as text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Whether rows beyond [`MAX_QUERY_ROWS`] were left out.
+    pub truncated: bool,
+}
+
+/// The event database.
+pub struct EventStore {
+    conn: Connection,
+}
+
+impl EventStore {
+    /// Open or create the store at `path`, applying pending migrations.
+    pub fn open(path: &Path) -> Result<Self, EventStoreError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        let mut store = Self { conn };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    /// Open an existing store for reading only; writes through
+    /// [`query`](Self::query) fail.
+    pub fn open_read_only(path: &Path) -> Result<Self, EventStoreError> {
+        if !path.exists() {
+            return Err(EventStoreError::Missing(path.to_path_buf()));
+        }
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+        Ok(Self { conn })
+    }
+
+    /// Schema version of the database: the number of migrations applied.
+    pub fn schema_version(&self) -> Result<usize, EventStoreError> {
+        let version: Option<i64> = self
+            .conn
+            .query_row("SELECT MAX(version) FROM schema_version", [], |row| {
+                row.get(0)
+            })
+            .optional()?
+            .flatten();
+        Ok(version.unwrap_or(0) as usize)
+    }
+
+    /// Apply the migrations the database has not seen. A database written
+    /// by a newer release is left as is; its extra columns are ignored.
+    fn migrate(&mut self) -> Result<(), EventStoreError> {
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS schema_version (
+                version INTEGER PRIMARY KEY,
+                applied_at TEXT NOT NULL
+            );",
+        )?;
+        let current = self.schema_version()?;
+        if current > MIGRATIONS.len() {
+            warn!(
+                version = current,
+                known = MIGRATIONS.len(),
+                "Event store was created by a newer release"
+            );
+            return Ok(());
+        }
+        let tx = self.conn.transaction()?;
+        for (i, sql) in MIGRATIONS.iter().enumerate().skip(current) {
+            tx.execute_batch(sql)?;
+            tx.execute(
+                "INSERT INTO schema_version (version, applied_at) VALUES (?1, ?2)",
+                params![(i + 1) as i64, ts(Utc::now())],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Write a batch of events in one transaction.
+    pub fn write(&mut self, records: &[EventRecord]) -> Result<(), EventStoreError> {
+        let tx = self.conn.transaction()?;
+        for record in records {
+            let at = ts(record.at);
+            let workspace = &record.workspace;
+            let id = |id: &Option<Uuid>| id.map(|id| id.to_string());
+            match &record.event {
+                Event::TaskStarted {
+                    task_id,
+                    session_id,
+                } => {
+                    tx.prepare_cached(
+                        "INSERT INTO task_events (ts, workspace, session_id, task_id, phase)
+                         VALUES (?1, ?2, ?3, ?4, 'started')",
+                    )?
+                    .execute(params![
+                        at,
+                        workspace,
+                        id(session_id),
+                        task_id.to_string()
+                    ])?;
+                }
+                Event::TaskFinished {
+                    task_id,
+                    session_id,
+                    route,
+                    success,
+                    duration_ms,
+                    iterations,
+                    input_tokens,
+                    output_tokens,
+                    cost_usd,
+                } => {
+                    tx.prepare_cached(
+                        "INSERT INTO task_events (ts, workspace, session_id, task_id, phase,
+                             route, success, duration_ms, iterations, input_tokens,
+                             output_tokens, cost_usd)
+                         VALUES (?1, ?2, ?3, ?4, 'finished', ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                    )?
+                    .execute(params![
+                        at,
+                        workspace,
+                        id(session_id),
+                        task_id.to_string(),
+                        route,
+                        success,
+                        *duration_ms as i64,
+                        *iterations as i64,
+                        *input_tokens as i64,
+                        *output_tokens as i64,
+                        cost_usd,
+                    ])?;
+                }
+                Event::ToolCall {
+                    task_id,
+                    session_id,
+                    tool,
+                    duration_ms,
+                    success,
+                    error_kind,
+                    detail,
+                } => {
+                    tx.prepare_cached(
+                        "INSERT INTO tool_calls (ts, workspace, session_id, task_id, tool,
+                             duration_ms, success, error_kind, detail)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    )?
+                    .execute(params![
+                        at,
+                        workspace,
+                        id(session_id),
+                        id(task_id),
+                        tool,
+                        *duration_ms as i64,
+                        success,
+                        error_kind,
+                        detail,
+                    ])?;
+                }
+                Event::LlmRequest {
+                    model,
+                    success,
+                    latency_ms,
+                    input_tokens,
+                    output_tokens,
+                    cost_usd,
+                } => {
+                    tx.prepare_cached(
+                        "INSERT INTO llm_requests (ts, workspace, model, success, latency_ms,
+                             input_tokens, output_tokens, cost_usd, priced)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    )?
+                    .execute(params![
+                        at,
+                        workspace,
+                        model,
+                        success,
+                        *latency_ms as i64,
+                        *input_tokens as i64,
+                        *output_tokens as i64,
+                        cost_usd.unwrap_or(0.0),
+                        cost_usd.is_some(),
+                    ])?;
+                    tx.prepare_cached(
+                        "INSERT INTO cost_rollups (day, workspace, model, requests,
+                             input_tokens, output_tokens, cost_usd, unpriced_requests)
+                         VALUES (substr(?1, 1, 10), ?2, ?3, 1, ?4, ?5, ?6, ?7)
+                         ON CONFLICT (day, workspace, model) DO UPDATE SET
+                             requests = requests + 1,
+                             input_tokens = input_tokens + excluded.input_tokens,
+                             output_tokens = output_tokens + excluded.output_tokens,
+                             cost_usd = cost_usd + excluded.cost_usd,
+                             unpriced_requests = unpriced_requests + excluded.unpriced_requests",
+                    )?
+                    .execute(params![
+                        at,
+                        workspace,
+                        model,
+                        *input_tokens as i64,
+                        *output_tokens as i64,
+                        cost_usd.unwrap_or(0.0),
+                        cost_usd.is_none() as i64,
+                    ])?;
+                }
+                Event::Approval {
+                    session_id,
+                    tool,
+                    approved,
+                } => {
+                    tx.prepare_cached(
+                        "INSERT INTO approvals (ts, workspace, session_id, tool, approved)
+                         VALUES (?1, ?2, ?3, ?4, ?5)",
+                    )?
+                    .execute(params![
+                        at,
+                        workspace,
+                        id(session_id),
+                        tool,
+                        approved
+                    ])?;
+                }
+                Event::SafetyBlock {
+                    session_id,
+                    tool,
+                    kind,
+                    reason,
+                } => {
+                    tx.prepare_cached(
+                        "INSERT INTO safety_blocks (ts, workspace, session_id, tool, kind, reason)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    )?
+                    .execute(params![
+                        at,
+                        workspace,
+                        id(session_id),
+                        tool,
+                        kind,
+                        reason
+                    ])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Delete events older than `retention_days` before `now`. Returns the
+    /// number of rows removed; 0 days keeps everything.
+    pub fn prune(&self, retention_days: u32, now: DateTime<Utc>) -> Result<usize, EventStoreError> {
+        if retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = ts(now - ChronoDuration::days(retention_days as i64));
+        let mut removed = 0;
+        for table in EVENT_TABLES {
+            removed += self.conn.execute(
+                &format!("DELETE FROM {} WHERE ts < ?1", table),
+                params![cutoff],
+            )?;
+        }
+        Ok(removed)
+    }
+
+    /// Delete every event recorded in `workspace`, and its cost rollups.
+    pub fn delete_workspace(&self, workspace: &str) -> Result<usize, EventStoreError> {
+        let mut removed = 0;
+        for table in EVENT_TABLES.iter().chain(&["cost_rollups"]) {
+            removed += self.conn.execute(
+                &format!("DELETE FROM {} WHERE workspace = ?1", table),
+                params![workspace],
+            )?;
+        }
+        Ok(removed)
+    }
+
+    /// Calls and failures per tool since `since`, most failures first.
+    pub fn tool_stats(&self, since: DateTime<Utc>) -> Result<Vec<ToolStats>, EventStoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tool, COUNT(*), SUM(success = 0), AVG(duration_ms)
+             FROM tool_calls WHERE ts >= ?1
+             GROUP BY tool ORDER BY SUM(success = 0) DESC, COUNT(*) DESC, tool",
+        )?;
+        let rows = stmt.query_map(params![ts(since)], |row| {
+            Ok(ToolStats {
+                tool: row.get(0)?,
+                calls: row.get::<_, i64>(1)? as u64,
+                failures: row.get::<_, i64>(2)? as u64,
+                avg_duration_ms: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Failed calls of `tool` since `since`, grouped by detail (e.g. the
+    /// shell command), most frequent first.
+    pub fn tool_failures(
+        &self,
+        tool: &str,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<FailureDetail>, EventStoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COALESCE(detail, ''), COUNT(*), MAX(error_kind), MAX(ts)
+             FROM tool_calls WHERE success = 0 AND tool = ?1 AND ts >= ?2
+             GROUP BY detail ORDER BY COUNT(*) DESC, MAX(ts) DESC LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![tool, ts(since), limit as i64], |row| {
+            Ok(FailureDetail {
+                detail: row.get(0)?,
+                failures: row.get::<_, i64>(1)? as u64,
+                error_kind: row.get(2)?,
+                last_seen: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// LLM requests, tokens and cost per day and model from the day of
+    /// `since` on, newest day first. Read from the rollups, so days past
+    /// retention are still covered.
+    pub fn cost_by_day(&self, since: DateTime<Utc>) -> Result<Vec<DailyCost>, EventStoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT day, model, SUM(requests), SUM(input_tokens), SUM(output_tokens),
+                 SUM(cost_usd), SUM(unpriced_requests)
+             FROM cost_rollups WHERE day >= ?1
+             GROUP BY day, model ORDER BY day DESC, SUM(cost_usd) DESC",
+        )?;
+        let rows = stmt.query_map(params![day(since)], daily_cost)?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// LLM usage per day and model in `month` (`YYYY-MM`), oldest day
+    /// first; only `workspace`'s when given.
+    pub fn month_by_day(
+        &self,
+        month: &str,
+        workspace: Option<&str>,
+    ) -> Result<Vec<DailyCost>, EventStoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT day, model, SUM(requests), SUM(input_tokens), SUM(output_tokens),
+                 SUM(cost_usd), SUM(unpriced_requests)
+             FROM cost_rollups
+             WHERE substr(day, 1, 7) = ?1 AND (?2 IS NULL OR workspace = ?2)
+             GROUP BY day, model ORDER BY day, model",
+        )?;
+        let rows = stmt.query_map(params![month, workspace], daily_cost)?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// LLM usage per workspace and model in `month` (`YYYY-MM`), most
+    /// expensive first.
+    pub fn month_by_workspace(&self, month: &str) -> Result<Vec<MonthlyCost>, EventStoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT workspace, model, SUM(requests), SUM(input_tokens), SUM(output_tokens),
+                 SUM(cost_usd), SUM(unpriced_requests)
+             FROM cost_rollups WHERE substr(day, 1, 7) = ?1
+             GROUP BY workspace, model
+             ORDER BY SUM(cost_usd) DESC, SUM(requests) DESC, workspace, model",
+        )?;
+        let rows = stmt.query_map(params![month], |row| {
+            Ok(MonthlyCost {
+                workspace: row.get(0)?,
+                model: row.get(1)?,
+                requests: row.get::<_, i64>(2)? as u64,
+                input_tokens: row.get::<_, i64>(3)? as u64,
+                output_tokens: row.get::<_, i64>(4)? as u64,
+                cost_usd: row.get(5)?,
+                unpriced_requests: row.get::<_, i64>(6)? as u64,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Workspaces with the most finished tasks since `since`.
+    pub fn busiest_workspaces(
+        &self,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<WorkspaceActivity>, EventStoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT workspace, SUM(tasks), SUM(tools), SUM(cost) FROM (
+                 SELECT workspace, 1 AS tasks, 0 AS tools, COALESCE(cost_usd, 0) AS cost
+                 FROM task_events WHERE phase = 'finished' AND ts >= ?1
+                 UNION ALL
+                 SELECT workspace, 0, 1, 0 FROM tool_calls WHERE ts >= ?1
+             )
+             GROUP BY workspace ORDER BY SUM(tasks) DESC, SUM(tools) DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![ts(since), limit as i64], |row| {
+            Ok(WorkspaceActivity {
+                workspace: row.get(0)?,
+                tasks: row.get::<_, i64>(1)? as u64,
+                tool_calls: row.get::<_, i64>(2)? as u64,
+                cost_usd: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Approval decisions per tool since `since`, most denials first.
+    pub fn approval_stats(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ApprovalStats>, EventStoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tool, SUM(approved = 1), SUM(approved = 0)
+             FROM approvals WHERE ts >= ?1
+             GROUP BY tool ORDER BY SUM(approved = 0) DESC, COUNT(*) DESC, tool",
+        )?;
+        let rows = stmt.query_map(params![ts(since)], |row| {
+            Ok(ApprovalStats {
+                tool: row.get(0)?,
+                approved: row.get::<_, i64>(1)? as u64,
+                denied: row.get::<_, i64>(2)? as u64,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Run an ad hoc query. On a store opened with
+    /// [`open_read_only`](Self::open_read_only) statements that write fail.
+    pub fn query(&self, sql: &str) -> Result<QueryResult, EventStoreError> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+        let mut rows = stmt.query([])?;
+        let mut result = QueryResult {
+            columns,
+            rows: Vec::new(),
+            truncated: false,
+        };
+        while let Some(row) = rows.next()? {
+            if result.rows.len() == MAX_QUERY_ROWS {
+                result.truncated = true;
+                break;
+            }
+            let values = (0..result.columns.len())
+                .map(|i| {
+                    Ok(match row.get_ref(i)? {
+                        ValueRef::Null => "NULL".to_string(),
+                        ValueRef::Integer(n) => n.to_string(),
+                        ValueRef::Real(f) => f.to_string(),
+                        ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned(),
+                        ValueRef::Blob(b) => format!("<{} bytes>", b.len()),
+                    })
+                })
+                .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+            result.rows.push(values);
+        }
+        Ok(result)
+    }
+}
+
+fn ts(at: DateTime<Utc>) -> String {
+    at.format(TS_FORMAT).to_string()
+}
+
+/// Day of `at` as stored in `cost_rollups`.
+fn day(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d").to_string()
+}
+
+fn daily_cost(row: &rusqlite::Row<'_>) -> rusqlite::Result<DailyCost> {
+    Ok(DailyCost {
+        day: row.get(0)?,
+        model: row.get(1)?,
+        requests: row.get::<_, i64>(2)? as u64,
+        input_tokens: row.get::<_, i64>(3)? as u64,
+        output_tokens: row.get::<_, i64>(4)? as u64,
+        cost_usd: row.get(5)?,
+        unpriced_requests: row.get::<_, i64>(6)? as u64,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Monthly cost
+// ---------------------------------------------------------------------------
+
+/// Calendar month (UTC) of `at`, as `YYYY-MM`.
+pub fn month_of(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m").to_string()
+}
+
+/// Whether `month` is a `YYYY-MM` month.
+pub fn is_month(month: &str) -> bool {
+    month.len() == 7
+        && chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").is_ok()
+}
+
+/// Cumulative spend over `month` from [`EventStore::month_by_day`] rows: a
+/// line for the total and one per model, with the soft cap marked.
+pub fn cost_chart(month: &str, days: &[DailyCost], cap_usd: Option<f64>) -> ChartSpec {
+    let mut labels: Vec<String> = days.iter().map(|d| d.day.clone()).collect();
+    labels.dedup();
+    let mut models: Vec<&str> = days.iter().map(|d| d.model.as_str()).collect();
+    models.sort_unstable();
+    models.dedup();
+
+    let cumulative = |model: Option<&str>| {
+        let mut total = 0.0;
+        labels
+            .iter()
+            .map(|label| {
+                total += days
+                    .iter()
+                    .filter(|d| &d.day == label && model.is_none_or(|m| d.model == m))
+                    .map(|d| d.cost_usd)
+                    .sum::<f64>();
+                total
+            })
+            .collect::<Vec<_>>()
+    };
+    let dataset = |label: &str, data| ChartDataset {
+        label: label.to_string(),
+        data,
+        color: None,
+        y_axis: Default::default(),
+    };
+    let mut datasets = vec![dataset("Total", cumulative(None))];
+    if models.len() > 1 {
+        datasets.extend(models.iter().map(|m| dataset(m, cumulative(Some(m)))));
+    }
+
+    ChartSpec {
+        chart_type: "line".into(),
+        labels,
+        datasets,
+        title: Some(format!("LLM spend, {}", month)),
+        x_axis: None,
+        y_axis: Some(AxisSpec {
+            title: Some("USD".into()),
+            min: Some(0.0),
+            ..Default::default()
+        }),
+        y2_axis: None,
+        annotations: cap_usd
+            .map(|cap| ChartAnnotation {
+                axis: AnnotationAxis::Y,
+                value: AxisValue::Number(cap),
+                label: Some("Soft cap".into()),
+                color: None,
+            })
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// A workspace's spend in a month against its `[budget]
+/// monthly_soft_cap_usd`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CostCapStatus {
+    pub month: String,
+    /// Cost of the priced requests.
+    pub spent_usd: f64,
+    pub cap_usd: f64,
+    /// Requests to models with unknown pricing, not included in `spent_usd`.
+    pub unpriced_requests: u64,
+}
+
+impl CostCapStatus {
+    pub fn exceeded(&self) -> bool {
+        self.spent_usd >= self.cap_usd
+    }
+
+    /// One line for the REPL prompt and logs.
+    pub fn warning(&self) -> String {
+        let unpriced = if self.unpriced_requests > 0 {
+            format!(" + {} unpriced request(s)", self.unpriced_requests)
+        } else {
+            String::new()
+        };
+        format!(
+            "{} LLM spend ${:.2}{} is over the ${:.2} monthly soft cap",
+            self.month, self.spent_usd, unpriced, self.cap_usd
+        )
+    }
+}
+
+/// Where to look up a workspace's spend for its monthly soft cap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostCap {
+    store_path: PathBuf,
+    workspace: String,
+    cap_usd: f64,
+}
+
+impl CostCap {
+    /// The soft cap of `workspace`, when `[budget] monthly_soft_cap_usd` is
+    /// set and the event store is enabled.
+    pub fn from_config(config: &crate::config::AgentConfig, workspace: &Path) -> Option<Self> {
+        let cap_usd = config.budget.as_ref()?.monthly_soft_cap_usd;
+        if cap_usd <= 0.0 || !config.event_store.enabled {
+            return None;
+        }
+        Some(Self {
+            store_path: config.event_store.resolved_path()?,
+            workspace: workspace.display().to_string(),
+            cap_usd,
+        })
+    }
+
+    /// Workspace the cap applies to, as recorded in the store.
+    pub fn workspace(&self) -> &str {
+        &self.workspace
+    }
+
+    /// Spend in the month of `now` so far. A store that was never written
+    /// counts as no spend.
+    pub fn status(&self, now: DateTime<Utc>) -> Result<CostCapStatus, EventStoreError> {
+        let month = month_of(now);
+        let days = match EventStore::open_read_only(&self.store_path) {
+            Ok(store) => store.month_by_day(&month, Some(&self.workspace))?,
+            Err(EventStoreError::Missing(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(CostCapStatus {
+            month,
+            spent_usd: days.iter().map(|d| d.cost_usd).sum(),
+            cap_usd: self.cap_usd,
+            unpriced_requests: days.iter().map(|d| d.unpriced_requests).sum(),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+enum Message {
+    Record(EventRecord),
+    /// Write everything queued so far, then acknowledge.
+    Flush(mpsc::Sender<()>),
+}
+
+struct Recorder {
+    tx: SyncSender<Message>,
+    workspace: String,
+    dropped: AtomicU64,
+}
+
+/// Replaced on each install, like the metrics instruments.
+static RECORDER: RwLock<Option<Arc<Recorder>>> = RwLock::new(None);
+
+fn recorder() -> Option<Arc<Recorder>> {
+    RECORDER.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Keeps the event store installed; dropping it writes queued events and
+/// stops recording.
+pub struct EventStoreGuard {
+    _private: (),
+}
+
+impl Drop for EventStoreGuard {
+    fn drop(&mut self) {
+        flush(Duration::from_secs(2));
+        RECORDER.write().unwrap_or_else(|e| e.into_inner()).take();
+    }
+}
+
+/// Open the store and start recording events from `workspace`, if
+/// `[event_store]` is enabled. Events older than the retention period are
+/// deleted now and once a day after.
+pub fn install(
+    config: &EventStoreConfig,
+    workspace: &Path,
+) -> Result<Option<EventStoreGuard>, EventStoreError> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    let path = config.resolved_path().ok_or(EventStoreError::NoDataDir)?;
+    let store = EventStore::open(&path)?;
+    let tx = spawn_writer(store, config)?;
+    *RECORDER.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(Recorder {
+        tx,
+        workspace: workspace.display().to_string(),
+        dropped: AtomicU64::new(0),
+    }));
+    Ok(Some(EventStoreGuard { _private: () }))
+}
+
+/// Queue an event for writing. A no-op unless the store is installed;
+/// never blocks.
+pub fn record(event: Event) {
+    let Some(recorder) = recorder() else {
+        return;
+    };
+    let record = EventRecord {
+        at: Utc::now(),
+        workspace: recorder.workspace.clone(),
+        event,
+    };
+    if let Err(TrySendError::Full(_)) = recorder.tx.try_send(Message::Record(record))
+        && recorder.dropped.fetch_add(1, Ordering::Relaxed) == 0
+    {
+        warn!("Event store queue is full; dropping events");
+    }
+}
+
+/// Record the audit events that are approval decisions or safety blocks.
+pub fn record_audit(session_id: Uuid, event: &AuditEvent) {
+    let session_id = Some(session_id);
+    let event = match event {
+        AuditEvent::ApprovalDecision { tool, approved } => Event::Approval {
+            session_id,
+            tool: tool.clone(),
+            approved: *approved,
+        },
+        AuditEvent::ActionDenied { tool, reason } => Event::SafetyBlock {
+            session_id,
+            tool: tool.clone(),
+            kind: "permission_denied".into(),
+            reason: reason.clone(),
+        },
+        AuditEvent::ContractViolation {
+            tool,
+            contract,
+            detail,
+            ..
+        } => Event::SafetyBlock {
+            session_id,
+            tool: tool.clone(),
+            kind: "contract_violation".into(),
+            reason: format!("{}: {}", contract, detail),
+        },
+        AuditEvent::CapabilityBlocked {
+            tool,
+            capability,
+            reason,
+            ..
+        } => Event::SafetyBlock {
+            session_id,
+            tool: tool.clone(),
+            kind: "capability_blocked".into(),
+            reason: format!("{}: {}", capability, reason),
+        },
+        AuditEvent::AgentViolation {
+            tool,
+            limit,
+            detail,
+            ..
+        } => Event::SafetyBlock {
+            session_id,
+            tool: tool.clone(),
+            kind: "agent_limit".into(),
+            reason: format!("{}: {}", limit, detail),
+        },
+        AuditEvent::ApprovalExpired { tool, .. } => Event::Approval {
+            session_id,
+            tool: tool.clone(),
+            approved: false,
+        },
+        _ => return,
+    };
+    record(event);
+}
+
+/// Wait up to `timeout` for queued events to be written. Returns false if
+/// the store is not installed or the writer did not answer in time.
+pub fn flush(timeout: Duration) -> bool {
+    let Some(recorder) = recorder() else {
+        return false;
+    };
+    let (ack_tx, ack_rx) = mpsc::channel();
+    recorder.tx.send(Message::Flush(ack_tx)).is_ok() && ack_rx.recv_timeout(timeout).is_ok()
+}
+
+/// Start the background thread that writes queued events to `store`.
+fn spawn_writer(
+    mut store: EventStore,
+    config: &EventStoreConfig,
+) -> Result<SyncSender<Message>, EventStoreError> {
+    const PRUNE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+    let (tx, rx) = mpsc::sync_channel::<Message>(config.queue_capacity.max(1));
+    let batch_size = config.batch_size.max(1);
+    let flush_interval = Duration::from_millis(config.flush_interval_ms);
+    let retention_days = config.retention_days;
+    std::thread::Builder::new()
+        .name("rustant-events".into())
+        .spawn(move || {
+            let mut last_prune: Option<Instant> = None;
+            let mut disconnected = false;
+            while !disconnected {
+                if last_prune.is_none_or(|at| at.elapsed() >= PRUNE_INTERVAL) {
+                    if let Err(e) = store.prune(retention_days, Utc::now()) {
+                        warn!("Event store retention failed: {}", e);
+                    }
+                    last_prune = Some(Instant::now());
+                }
+
+                // Block for the first message, then gather more until the
+                // batch is full, the interval passes or a flush is asked for.
+                let mut batch = Vec::new();
+                let mut acks = Vec::new();
+                let mut next = match rx.recv() {
+                    Ok(message) => Some(message),
+                    Err(_) => break,
+                };
+                let deadline = Instant::now() + flush_interval;
+                while let Some(message) = next.take() {
+                    match message {
+                        Message::Record(record) => batch.push(record),
+                        Message::Flush(ack) => acks.push(ack),
+                    }
+                    if !acks.is_empty() || batch.len() >= batch_size {
+                        break;
+                    }
+                    match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                        Ok(message) => next = Some(message),
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => disconnected = true,
+                    }
+                }
+                if !batch.is_empty()
+                    && let Err(e) = store.write(&batch)
+                {
+                    warn!(events = batch.len(), "Event store write failed: {}", e);
+                }
+                for ack in acks {
+                    let _ = ack.send(());
+                }
+            }
+        })?;
+    Ok(tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn at(days_ago: i64) -> DateTime<Utc> {
+        Utc::now() - ChronoDuration::days(days_ago)
+    }
+
+    fn record(workspace: &str, days_ago: i64, event: Event) -> EventRecord {
+        EventRecord {
+            at: at(days_ago),
+            workspace: workspace.into(),
+            event,
+        }
+    }
+
+    fn tool_call(tool: &str, success: bool, detail: &str) -> Event {
+        Event::ToolCall {
+            task_id: None,
+            session_id: None,
+            tool: tool.into(),
+            duration_ms: 100,
+            success,
+            error_kind: (!success).then(|| "internal".to_string()),
+            detail: Some(detail.into()),
+        }
+    }
+
+    fn open() -> (tempfile::TempDir, EventStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EventStore::open(&dir.path().join("events.db")).unwrap();
+        (dir, store)
+    }
+
+    #[test]
+    fn test_migrations_are_recorded() {
+        let (dir, store) = open();
+        assert_eq!(store.schema_version().unwrap(), MIGRATIONS.len());
+        drop(store);
+        // Reopening applies nothing new.
+        let store = EventStore::open(&dir.path().join("events.db")).unwrap();
+        assert_eq!(store.schema_version().unwrap(), MIGRATIONS.len());
+        let versions: i64 = store
+            .conn
+            .query_row("SELECT COUNT(*) FROM schema_version", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(versions, MIGRATIONS.len() as i64);
+    }
+
+    #[test]
+    fn test_upgrade_backfills_cost_rollups() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.db");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE schema_version (version INTEGER PRIMARY KEY, applied_at TEXT NOT NULL);
+             INSERT INTO schema_version VALUES (1, '2025-01-01 00:00:00');
+             INSERT INTO llm_requests (ts, workspace, model, success, latency_ms,
+                 input_tokens, output_tokens, cost_usd)
+             VALUES ('2025-01-02 10:00:00', '/a', 'gpt-4o', 1, 100, 10, 5, 0.5),
+                    ('2025-01-02 11:00:00', '/a', 'gpt-4o', 1, 100, 10, 5, 0.25);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let store = EventStore::open(&path).unwrap();
+        let months = store.month_by_workspace("2025-01").unwrap();
+        assert_eq!(months.len(), 1);
+        assert_eq!((months[0].requests, months[0].input_tokens), (2, 20));
+        assert!((months[0].cost_usd - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tool_failure_reports() {
+        let (_dir, mut store) = open();
+        store
+            .write(&[
+                record("/a", 0, tool_call("shell_exec", false, "cargo test")),
+                record("/a", 1, tool_call("shell_exec", false, "cargo test")),
+                record("/a", 1, tool_call("shell_exec", false, "make")),
+                record("/a", 1, tool_call("shell_exec", true, "ls")),
+                record("/a", 1, tool_call("file_read", true, "src/lib.rs")),
+                record("/a", 30, tool_call("shell_exec", false, "old")),
+            ])
+            .unwrap();
+
+        let stats = store.tool_stats(at(7)).unwrap();
+        assert_eq!(stats[0].tool, "shell_exec");
+        assert_eq!((stats[0].calls, stats[0].failures), (4, 3));
+        assert_eq!(stats[0].failure_rate(), 0.75);
+        assert_eq!(stats[1].failures, 0);
+
+        let failures = store.tool_failures("shell_exec", at(7), 10).unwrap();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].detail, "cargo test");
+        assert_eq!(failures[0].failures, 2);
+        assert_eq!(failures[0].error_kind.as_deref(), Some("internal"));
+    }
+
+    #[test]
+    fn test_cost_workspace_and_approval_reports() {
+        let (_dir, mut store) = open();
+        let llm = |model: &str, cost: f64| Event::LlmRequest {
+            model: model.into(),
+            success: true,
+            latency_ms: 800,
+            input_tokens: 1000,
+            output_tokens: 200,
+            cost_usd: Some(cost),
+        };
+        let finished = |cost: f64| Event::TaskFinished {
+            task_id: Uuid::new_v4(),
+            session_id: None,
+            route: "full".into(),
+            success: true,
+            duration_ms: 5000,
+            iterations: 3,
+            input_tokens: 2000,
+            output_tokens: 400,
+            cost_usd: cost,
+        };
+        let approval = |tool: &str, approved: bool| Event::Approval {
+            session_id: None,
+            tool: tool.into(),
+            approved,
+        };
+        store
+            .write(&[
+                record("/a", 0, llm("gpt-4o", 0.02)),
+                record("/a", 0, llm("gpt-4o", 0.03)),
+                record("/b", 0, llm("claude", 0.10)),
+                record("/a", 0, finished(0.05)),
+                record("/b", 0, finished(0.10)),
+                record("/b", 0, finished(0.01)),
+                record("/a", 0, tool_call("git_commit", true, "")),
+                record("/a", 0, approval("shell_exec", true)),
+                record("/a", 0, approval("shell_exec", false)),
+                record("/a", 0, approval("file_write", true)),
+            ])
+            .unwrap();
+
+        let costs = store.cost_by_day(at(1)).unwrap();
+        assert_eq!(costs.len(), 2);
+        assert_eq!(costs[0].model, "claude");
+        assert_eq!(costs[1].requests, 2);
+        assert!((costs[1].cost_usd - 0.05).abs() < 1e-9);
+        assert_eq!(costs[1].input_tokens, 2000);
+
+        let busiest = store.busiest_workspaces(at(1), 10).unwrap();
+        assert_eq!(busiest[0].workspace, "/b");
+        assert_eq!(busiest[0].tasks, 2);
+        assert_eq!(busiest[1].tool_calls, 1);
+
+        let approvals = store.approval_stats(at(1)).unwrap();
+        assert_eq!(approvals[0].tool, "shell_exec");
+        assert_eq!(approvals[0].deny_rate(), 0.5);
+        assert_eq!(approvals[1].denied, 0);
+    }
+
+    #[test]
+    fn test_retention_and_workspace_deletion() {
+        let (_dir, mut store) = open();
+        store
+            .write(&[
+                record("/a", 100, tool_call("echo", true, "")),
+                record("/a", 1, tool_call("echo", true, "")),
+                record("/b", 1, tool_call("echo", true, "")),
+            ])
+            .unwrap();
+        assert_eq!(store.prune(0, Utc::now()).unwrap(), 0);
+        assert_eq!(store.prune(90, Utc::now()).unwrap(), 1);
+        assert_eq!(store.delete_workspace("/a").unwrap(), 1);
+        assert_eq!(store.tool_stats(at(365)).unwrap()[0].calls, 1);
+    }
+
+    fn llm_at(at: DateTime<Utc>, workspace: &str, model: &str, cost: Option<f64>) -> EventRecord {
+        EventRecord {
+            at,
+            workspace: workspace.into(),
+            event: Event::LlmRequest {
+                model: model.into(),
+                success: true,
+                latency_ms: 500,
+                input_tokens: 100,
+                output_tokens: 50,
+                cost_usd: cost,
+            },
+        }
+    }
+
+    #[test]
+    fn test_monthly_rollups_survive_retention() {
+        let (dir, mut store) = open();
+        let jan = |d: u32| Utc.with_ymd_and_hms(2025, 1, d, 12, 0, 0).unwrap();
+        store
+            .write(&[
+                llm_at(jan(3), "/a", "gpt-4o", Some(0.50)),
+                llm_at(jan(3), "/a", "gpt-4o", Some(0.25)),
+                llm_at(jan(9), "/a", "local-llama", None),
+                llm_at(jan(9), "/b", "gpt-4o", Some(2.00)),
+                llm_at(
+                    Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap(),
+                    "/a",
+                    "gpt-4o",
+                    Some(9.0),
+                ),
+            ])
+            .unwrap();
+
+        // Retention deletes the raw events but not the rollups.
+        assert_eq!(store.prune(30, Utc::now()).unwrap(), 5);
+        drop(store);
+        let store = EventStore::open(&dir.path().join("events.db")).unwrap();
+
+        let months = store.month_by_workspace("2025-01").unwrap();
+        assert_eq!(months.len(), 3);
+        assert_eq!(
+            (months[0].workspace.as_str(), months[0].cost_usd),
+            ("/b", 2.00)
+        );
+        assert_eq!(months[1].requests, 2);
+        assert!((months[1].cost_usd - 0.75).abs() < 1e-9);
+        assert_eq!(months[2].model, "local-llama");
+        assert_eq!(months[2].unpriced_requests, 1);
+
+        let days = store.month_by_day("2025-01", Some("/a")).unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].day, "2025-01-03");
+        assert_eq!(
+            format_cost(
+                days[1].cost_usd,
+                days[1].requests,
+                days[1].unpriced_requests
+            ),
+            "unknown"
+        );
+        let chart = cost_chart("2025-01", &days, Some(1.0));
+        assert_eq!(chart.labels, vec!["2025-01-03", "2025-01-09"]);
+        assert_eq!(chart.datasets[0].data, vec![0.75, 0.75]);
+        assert_eq!(chart.annotations.len(), 1);
+
+        assert_eq!(store.delete_workspace("/a").unwrap(), 3);
+        assert_eq!(store.month_by_workspace("2025-01").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_cost_cap_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = crate::config::AgentConfig::default();
+        config.event_store.path = Some(dir.path().join("events.db"));
+        assert!(CostCap::from_config(&config, Path::new("/a")).is_none());
+        config.event_store.enabled = true;
+        config.budget = Some(crate::config::BudgetConfig {
+            monthly_soft_cap_usd: 1.0,
+            ..Default::default()
+        });
+        let cap = CostCap::from_config(&config, Path::new("/a")).unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 1, 20, 8, 0, 0).unwrap();
+
+        // No store yet: nothing spent.
+        let status = cap.status(now).unwrap();
+        assert_eq!((status.month.as_str(), status.spent_usd), ("2025-01", 0.0));
+        assert!(!status.exceeded());
+
+        let mut store = EventStore::open(&dir.path().join("events.db")).unwrap();
+        store
+            .write(&[
+                llm_at(now, "/a", "gpt-4o", Some(0.60)),
+                llm_at(now, "/a", "gpt-4o", Some(0.60)),
+                llm_at(now, "/a", "mystery", None),
+                llm_at(now, "/b", "gpt-4o", Some(5.0)),
+            ])
+            .unwrap();
+        let status = cap.status(now).unwrap();
+        assert!(status.exceeded());
+        assert_eq!(status.unpriced_requests, 1);
+        assert_eq!(
+            status.warning(),
+            "2025-01 LLM spend $1.20 + 1 unpriced request(s) is over the $1.00 monthly soft cap"
+        );
+        assert!(
+            !cap.status(now + ChronoDuration::days(15))
+                .unwrap()
+                .exceeded()
+        );
+    }
+
+    #[test]
+    fn test_months() {
+        assert!(is_month("2025-01"));
+        assert!(!is_month("2025-13"));
+        assert!(!is_month("2025-1"));
+        assert!(!is_month("January"));
+        assert_eq!(format_cost(1.5, 3, 0), "$1.5000");
+        assert_eq!(format_cost(1.5, 3, 1), "≥$1.5000");
+    }
+
+    #[test]
+    fn test_ad_hoc_queries_are_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.db");
+        EventStore::open(&path)
+            .unwrap()
+            .write(&[record("/a", 0, tool_call("shell_exec", false, "ls"))])
+            .unwrap();
+
+        let store = EventStore::open_read_only(&path).unwrap();
+        let result = store
+            .query("SELECT tool, success, detail, error_kind FROM tool_calls")
+            .unwrap();
+        assert_eq!(
+            result.columns,
+            vec!["tool", "success", "detail", "error_kind"]
+        );
+        assert_eq!(result.rows, vec![vec!["shell_exec", "0", "ls", "internal"]]);
+        assert!(store.query("DELETE FROM tool_calls").is_err());
+        assert!(matches!(
+            EventStore::open_read_only(&dir.path().join("missing.db")),
+            Err(EventStoreError::Missing(_))
+        ));
+    }
+
+    #[test]
+    fn test_writer_batches_and_flushes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.db");
+        let config = EventStoreConfig {
+            batch_size: 2,
+            flush_interval_ms: 60_000,
+            ..Default::default()
+        };
+        let tx = spawn_writer(EventStore::open(&path).unwrap(), &config).unwrap();
+        for detail in ["a", "b", "c"] {
+            tx.send(Message::Record(record(
+                "/a",
+                0,
+                tool_call("echo", true, detail),
+            )))
+            .unwrap();
+        }
+        let (ack_tx, ack_rx) = mpsc::channel();
+        tx.send(Message::Flush(ack_tx)).unwrap();
+        ack_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        let store = EventStore::open_read_only(&path).unwrap();
+        assert_eq!(store.tool_stats(at(1)).unwrap()[0].calls, 3);
+    }
+
+    #[test]
+    fn test_call_detail() {
+        assert_eq!(
+            call_detail(&json!({"command": "cargo test", "path": "x"})).as_deref(),
+            Some("cargo test")
+        );
+        assert_eq!(call_detail(&json!({"n": 1})), None);
+        let long = "x".repeat(MAX_DETAIL_CHARS + 5);
+        assert_eq!(
+            call_detail(&json!({ "path": long }))
+                .unwrap()
+                .chars()
+                .count(),
+            MAX_DETAIL_CHARS + 1
+        );
+    }
+
+    #[test]
+    fn test_audit_events_map_to_blocks() {
+        // Without an installed store, recording is a no-op.
+        record_audit(
+            Uuid::new_v4(),
+            &AuditEvent::ActionDenied {
+                tool: "shell_exec".into(),
+                reason: "denied".into(),
+            },
+        );
+        assert!(!flush(Duration::from_millis(10)));
+    }
+}
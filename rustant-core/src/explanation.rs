@@ -65,6 +65,13 @@ pub enum DecisionType {
         /// The recovery strategy chosen.
         strategy: String,
     },
+    /// The agent chose how to run a task (fast path or full pipeline).
+    TaskRouting {
+        /// The route taken: `fast_path`, `fallback` or `full`.
+        route: String,
+        /// Tools offered on the chosen route.
+        tools: Vec<String>,
+    },
 }
 
 /// A single step in the agent's reasoning chain.
@@ -356,14 +363,18 @@ mod tests {
             error: "timeout".into(),
             strategy: "retry".into(),
         };
+        let routing = DecisionType::TaskRouting {
+            route: "fast_path".into(),
+            tools: vec!["calculator".into()],
+        };
 
-        let jsons: Vec<String> = [&tool, &param, &decomp, &recovery]
+        let jsons: Vec<String> = [&tool, &param, &decomp, &recovery, &routing]
             .iter()
             .map(|v| serde_json::to_string(v).unwrap())
             .collect();
 
         let unique: std::collections::HashSet<&String> = jsons.iter().collect();
-        assert_eq!(unique.len(), 4);
+        assert_eq!(unique.len(), 5);
     }
 
     // -- Scenario-oriented tests --------------------------------------------
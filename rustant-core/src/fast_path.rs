@@ -0,0 +1,291 @@
+//! Fast path — trivial tasks answered without the full pipeline.
+//!
+//! A cheap heuristic classifier spots short, self-contained requests that
+//! need at most one tool: arithmetic and percentages (`calculator`), the
+//! current time or date (`datetime`), and greetings (no tool at all). The
+//! agent runs those under [`FAST_PATH_SYSTEM_PROMPT`] with only the route's
+//! tool schemas, skipping plan generation, council deliberation, knowledge
+//! rules and conversation history. If the model reaches for a tool the
+//! route does not offer, or needs more than one tool round, the task falls
+//! back to the full pipeline.
+
+use serde::{Deserialize, Serialize};
+
+/// System prompt used on the fast path.
+pub const FAST_PATH_SYSTEM_PROMPT: &str = "You are Rustant, a helpful assistant. \
+Answer the user's request directly and concisely. If a tool is available and \
+needed, call it once, then answer in one or two sentences.";
+
+/// Fast-path routing configuration (`[fast_path]`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FastPathConfig {
+    /// Route trivial tasks through the fast path.
+    pub enabled: bool,
+    /// Tasks longer than this many characters always take the full pipeline.
+    #[serde(default = "default_max_task_chars")]
+    pub max_task_chars: usize,
+}
+
+fn default_max_task_chars() -> usize {
+    160
+}
+
+impl Default for FastPathConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_task_chars: default_max_task_chars(),
+        }
+    }
+}
+
+/// Kind of trivial task recognised by [`classify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FastRoute {
+    /// Arithmetic or a percentage, e.g. "what's 17% of 2,340".
+    Arithmetic,
+    /// The current time or date, e.g. "what time is it in Tokyo".
+    Clock,
+    /// A greeting or thanks that needs no tool.
+    Smalltalk,
+}
+
+impl FastRoute {
+    /// Short name used in explanations and logs.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FastRoute::Arithmetic => "arithmetic",
+            FastRoute::Clock => "clock",
+            FastRoute::Smalltalk => "smalltalk",
+        }
+    }
+
+    /// Tools offered on this route.
+    pub fn tools(&self) -> &'static [&'static str] {
+        match self {
+            FastRoute::Arithmetic => &["calculator"],
+            FastRoute::Clock => &["datetime"],
+            FastRoute::Smalltalk => &[],
+        }
+    }
+}
+
+/// Words that chain steps or lean on earlier turns; such tasks need the
+/// full pipeline and its conversation history.
+const CONTEXT_WORDS: &[&str] = &[
+    "then", "also", "after", "again", "that", "this", "those", "these", "it's", "same", "previous",
+    "above", "earlier", "file", "files",
+];
+
+const SMALLTALK: &[&str] = &[
+    "hi",
+    "hello",
+    "hey",
+    "hi there",
+    "hello there",
+    "good morning",
+    "good afternoon",
+    "good evening",
+    "thanks",
+    "thank you",
+    "thanks a lot",
+    "thank you very much",
+    "cheers",
+];
+
+const CLOCK_PHRASES: &[&str] = &[
+    "what time is it",
+    "what's the time",
+    "current time",
+    "time right now",
+    "time is it in",
+    "what's the date",
+    "what is the date",
+    "today's date",
+    "what day is it",
+    "what day is today",
+    "day of the week is it",
+];
+
+/// Filler and operator words allowed around the numbers of an arithmetic task.
+const ARITHMETIC_WORDS: &[&str] = &[
+    "what",
+    "what's",
+    "whats",
+    "is",
+    "calculate",
+    "compute",
+    "evaluate",
+    "how",
+    "much",
+    "please",
+    "of",
+    "percent",
+    "plus",
+    "minus",
+    "times",
+    "x",
+    "divided",
+    "by",
+    "over",
+    "multiplied",
+    "squared",
+    "cubed",
+    "sqrt",
+    "square",
+    "root",
+    "to",
+    "the",
+    "power",
+    "equals",
+    "mod",
+];
+
+const OPERATOR_WORDS: &[&str] = &[
+    "percent",
+    "plus",
+    "minus",
+    "times",
+    "x",
+    "divided",
+    "over",
+    "multiplied",
+    "squared",
+    "cubed",
+    "sqrt",
+    "root",
+    "power",
+    "mod",
+];
+
+/// Classify a task, returning a route when it is trivial enough for the
+/// fast path.
+pub fn classify(task: &str, max_chars: usize) -> Option<FastRoute> {
+    let task = task.trim();
+    if task.is_empty()
+        || task.chars().count() > max_chars
+        || task.contains('\n')
+        || task.contains("```")
+        || task.starts_with('/')
+    {
+        return None;
+    }
+    let lower = task.to_lowercase().replace('’', "'");
+    let text = lower.trim_end_matches(['?', '!', '.', ' ']);
+
+    // More than one sentence is more than one step.
+    if text.contains(". ") || text.contains("? ") || text.contains("! ") || text.contains(';') {
+        return None;
+    }
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| c == ',' || c == '?' || c == '!'))
+        .collect();
+    if words.first() == Some(&"and")
+        || text.starts_with("what about")
+        || text.starts_with("how about")
+        || words.iter().any(|w| CONTEXT_WORDS.contains(w))
+    {
+        return None;
+    }
+
+    if SMALLTALK.contains(&text.trim_end_matches(',')) {
+        return Some(FastRoute::Smalltalk);
+    }
+    if CLOCK_PHRASES.iter().any(|p| text.contains(p)) {
+        return Some(FastRoute::Clock);
+    }
+    if is_arithmetic(text) {
+        return Some(FastRoute::Arithmetic);
+    }
+    None
+}
+
+/// Whether `text` is only numbers, operators and a few filler words.
+fn is_arithmetic(text: &str) -> bool {
+    let numeric = |c: char| c.is_ascii_digit() || "+-*/^()%×÷=.,".contains(c);
+    let mut has_digit = false;
+    let mut has_operator = false;
+    for word in text.split_whitespace() {
+        if ARITHMETIC_WORDS.contains(&word) {
+            has_operator |= OPERATOR_WORDS.contains(&word);
+        } else if word.chars().all(numeric) {
+            has_digit |= word.chars().any(|c| c.is_ascii_digit());
+            // A leading minus or trailing comma is not an operation.
+            has_operator |= word
+                .trim_start_matches('-')
+                .trim_end_matches([',', '.'])
+                .chars()
+                .any(|c| "+-*/^%×÷".contains(c));
+        } else {
+            return false;
+        }
+    }
+    has_digit && has_operator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(task: &str) -> Option<FastRoute> {
+        classify(task, FastPathConfig::default().max_task_chars)
+    }
+
+    #[test]
+    fn test_arithmetic_tasks() {
+        assert_eq!(route("what's 17% of 2,340"), Some(FastRoute::Arithmetic));
+        assert_eq!(route("What is 12 * (3 + 4)?"), Some(FastRoute::Arithmetic));
+        assert_eq!(route("calculate 2^10"), Some(FastRoute::Arithmetic));
+        assert_eq!(
+            route("how much is 340 divided by 8"),
+            Some(FastRoute::Arithmetic)
+        );
+        assert_eq!(route("what is 42"), None);
+        assert_eq!(route("what's 17% of my budget"), None);
+    }
+
+    #[test]
+    fn test_clock_tasks() {
+        assert_eq!(route("what time is it in Tokyo"), Some(FastRoute::Clock));
+        assert_eq!(route("What's the date today?"), Some(FastRoute::Clock));
+        assert_eq!(route("what day is it"), Some(FastRoute::Clock));
+    }
+
+    #[test]
+    fn test_smalltalk() {
+        assert_eq!(route("Hello!"), Some(FastRoute::Smalltalk));
+        assert_eq!(route("thanks"), Some(FastRoute::Smalltalk));
+        assert_eq!(route("Say hello"), None);
+    }
+
+    #[test]
+    fn test_multi_step_and_follow_ups_take_full_pipeline() {
+        assert_eq!(route("what's 2+2, then email the result to Sam"), None);
+        assert_eq!(route("what time is it? Book a meeting at 3"), None);
+        assert_eq!(route("what's 10% of that"), None);
+        assert_eq!(route("and what time is it in Paris"), None);
+        assert_eq!(route("Echo step 1, then calculate 2+2"), None);
+        assert_eq!(route("what's 2+2\nand 3+3"), None);
+        assert_eq!(route("/help"), None);
+    }
+
+    #[test]
+    fn test_long_tasks_take_full_pipeline() {
+        let task = format!("what is {}", "1 + ".repeat(60) + "1");
+        assert_eq!(route(&task), None);
+        assert_eq!(classify(&task, 1000), Some(FastRoute::Arithmetic));
+    }
+
+    #[test]
+    fn test_routes_offer_at_most_two_tools() {
+        for r in [
+            FastRoute::Arithmetic,
+            FastRoute::Clock,
+            FastRoute::Smalltalk,
+        ] {
+            assert!(r.tools().len() <= 2);
+        }
+        assert_eq!(FastRoute::Clock.tools(), &["datetime"]);
+    }
+}
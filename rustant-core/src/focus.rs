@@ -0,0 +1,523 @@
+//! Focus blocks — protected time while a pomodoro runs.
+//!
+//! Starting a pomodoro opens a focus block, kept with the pomodoro history in
+//! `.rustant/pomodoro/state.json`. While the block is open:
+//!
+//! - channel messages below `[focus] hold_below` are held by
+//!   [`FocusState::screen`] and delivered as one digest when the block ends.
+//!   Messages at or above it, and messages from `allow_senders`, get through
+//!   and are logged as interruptions.
+//! - scheduled jobs not marked `critical` wait until the block ends.
+//! - the gateway reports the block in `/api/status`.
+//!
+//! A block that outlives its planned end, e.g. because Rustant crashed during
+//! it, is closed by [`FocusState::reconcile`] at the next startup, so Do Not
+//! Disturb is not left on.
+
+use crate::channels::{ChannelMessage, ChannelUser, ClassifiedMessage};
+use crate::config::MessagePriority;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Characters of a held message kept for the digest.
+const MAX_HELD_CHARS: usize = 200;
+/// Finished blocks kept in the history.
+const MAX_HISTORY: usize = 500;
+
+/// Focus block settings (`[focus]` in config).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusConfig {
+    /// Turn on macOS Do Not Disturb for the block (when it isn't on already).
+    #[serde(default = "default_true")]
+    pub dnd: bool,
+    /// Channel messages below this priority are held until the block ends.
+    #[serde(default = "default_hold_below")]
+    pub hold_below: MessagePriority,
+    /// Senders (ID or display name) whose messages always get through.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_senders: Vec<String>,
+    /// Hold scheduled jobs not marked `critical` until the block ends.
+    #[serde(default = "default_true")]
+    pub pause_jobs: bool,
+    /// Channel the digest of held messages is sent to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<FocusDigestTarget>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_hold_below() -> MessagePriority {
+    MessagePriority::Urgent
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self {
+            dnd: true,
+            hold_below: default_hold_below(),
+            allow_senders: Vec::new(),
+            pause_jobs: true,
+            digest: None,
+        }
+    }
+}
+
+/// Where the held-message digest is sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusDigestTarget {
+    /// Channel name as registered in `[channels]` (e.g. "slack").
+    pub channel: String,
+    /// Conversation/chat ID on that channel.
+    #[serde(default)]
+    pub destination_id: String,
+}
+
+/// A channel message held during a focus block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeldMessage {
+    pub channel: String,
+    pub sender: String,
+    pub text: String,
+    pub priority: MessagePriority,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Why a message was let through during a focus block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterruptReason {
+    /// Classified at or above `hold_below`.
+    Urgent,
+    /// From a sender in `allow_senders`.
+    AllowedSender,
+}
+
+/// A message that reached the user during a focus block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interruption {
+    pub at: DateTime<Utc>,
+    pub channel: String,
+    pub sender: String,
+    pub reason: InterruptReason,
+}
+
+/// What to do with a channel message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusDecision {
+    /// No focus block is open.
+    Deliver,
+    /// Held for the end-of-block digest.
+    Hold,
+    /// Delivered through the block and logged.
+    Interrupt(InterruptReason),
+}
+
+/// The open focus block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusBlock {
+    pub task: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_mins: u32,
+    /// Whether the block turned Do Not Disturb on, and must turn it off.
+    #[serde(default)]
+    pub dnd: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub held: Vec<HeldMessage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interruptions: Vec<Interruption>,
+}
+
+impl FocusBlock {
+    /// Planned end of the block.
+    pub fn ends_at(&self) -> DateTime<Utc> {
+        self.started_at + Duration::minutes(self.duration_mins as i64)
+    }
+
+    /// One line for status displays, e.g. "In a focus block on 'refactor'
+    /// until 14:25".
+    pub fn status_line(&self) -> String {
+        format!(
+            "In a focus block on '{}' until {}",
+            self.task,
+            self.ends_at().with_timezone(&chrono::Local).format("%H:%M")
+        )
+    }
+
+    /// Digest of the messages held during the block, if there were any.
+    pub fn digest(&self) -> Option<String> {
+        if self.held.is_empty() {
+            return None;
+        }
+        let mut text = format!(
+            "{} message(s) held during your focus block on '{}':\n",
+            self.held.len(),
+            self.task
+        );
+        for msg in &self.held {
+            text.push_str(&format!(
+                "- [{}] {} ({}): {}\n",
+                msg.channel,
+                msg.sender,
+                msg.received_at
+                    .with_timezone(&chrono::Local)
+                    .format("%H:%M"),
+                msg.text
+            ));
+        }
+        Some(text)
+    }
+}
+
+/// A finished focus block, for history and reports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusRecord {
+    pub task: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_mins: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    pub completed: bool,
+    #[serde(default)]
+    pub interruptions: usize,
+    #[serde(default)]
+    pub held: usize,
+}
+
+/// The open block and finished ones of a workspace.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FocusState {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<FocusBlock>,
+    #[serde(default)]
+    pub history: Vec<FocusRecord>,
+}
+
+impl FocusState {
+    pub fn path(workspace: &Path) -> PathBuf {
+        workspace
+            .join(".rustant")
+            .join("pomodoro")
+            .join("state.json")
+    }
+
+    /// Load the workspace's state, or start empty.
+    pub fn load(workspace: &Path) -> Self {
+        std::fs::read_to_string(Self::path(workspace))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, workspace: &Path) -> std::io::Result<()> {
+        let path = Self::path(workspace);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &path)
+    }
+
+    /// The block open at `now`; a block past its planned end no longer counts.
+    pub fn active_at(&self, now: DateTime<Utc>) -> Option<&FocusBlock> {
+        self.active.as_ref().filter(|b| now < b.ends_at())
+    }
+
+    /// Open a block. Returns `false` when one is already open.
+    pub fn begin(&mut self, task: &str, duration_mins: u32, now: DateTime<Utc>) -> bool {
+        if self.active.is_some() {
+            return false;
+        }
+        self.active = Some(FocusBlock {
+            task: task.to_string(),
+            started_at: now,
+            duration_mins,
+            dnd: false,
+            held: Vec::new(),
+            interruptions: Vec::new(),
+        });
+        true
+    }
+
+    /// Close the open block, recording it in the history.
+    pub fn end(&mut self, now: DateTime<Utc>) -> Option<FocusBlock> {
+        let block = self.active.take()?;
+        self.history.push(FocusRecord {
+            task: block.task.clone(),
+            started_at: block.started_at,
+            duration_mins: block.duration_mins,
+            completed_at: Some(now.min(block.ends_at()).max(block.started_at)),
+            completed: true,
+            interruptions: block.interruptions.len(),
+            held: block.held.len(),
+        });
+        if self.history.len() > MAX_HISTORY {
+            self.history.drain(0..self.history.len() - MAX_HISTORY);
+        }
+        Some(block)
+    }
+
+    /// Close a block left open past its planned end. The caller reverts Do
+    /// Not Disturb if the returned block turned it on and delivers its digest.
+    pub fn reconcile(&mut self, now: DateTime<Utc>) -> Option<FocusBlock> {
+        match &self.active {
+            Some(block) if now >= block.ends_at() => self.end(now),
+            _ => None,
+        }
+    }
+
+    /// Decide whether a classified channel message reaches the user now.
+    /// Held messages and interruptions are added to the open block; the
+    /// caller saves the state.
+    pub fn screen(
+        &mut self,
+        classified: &ClassifiedMessage,
+        channel: &str,
+        config: &FocusConfig,
+        now: DateTime<Utc>,
+    ) -> FocusDecision {
+        if self.active_at(now).is_none() {
+            return FocusDecision::Deliver;
+        }
+        let Some(block) = self.active.as_mut() else {
+            return FocusDecision::Deliver;
+        };
+        let msg = &classified.original;
+        let sender = sender_name(&msg.sender);
+        let reason = if classified.priority >= config.hold_below {
+            Some(InterruptReason::Urgent)
+        } else if is_allowed(&msg.sender, &config.allow_senders) {
+            Some(InterruptReason::AllowedSender)
+        } else {
+            None
+        };
+        match reason {
+            Some(reason) => {
+                block.interruptions.push(Interruption {
+                    at: now,
+                    channel: channel.to_string(),
+                    sender,
+                    reason,
+                });
+                FocusDecision::Interrupt(reason)
+            }
+            None => {
+                block.held.push(HeldMessage {
+                    channel: channel.to_string(),
+                    sender,
+                    text: clip(msg.content.as_text().unwrap_or("(non-text message)")),
+                    priority: classified.priority,
+                    received_at: now,
+                });
+                FocusDecision::Hold
+            }
+        }
+    }
+
+    /// Focus time and interruptions over the seven days before `now`.
+    pub fn weekly_report(&self, now: DateTime<Utc>) -> String {
+        let since = now - Duration::days(7);
+        let blocks: Vec<&FocusRecord> = self
+            .history
+            .iter()
+            .filter(|r| r.started_at >= since)
+            .collect();
+        if blocks.is_empty() {
+            return "No focus blocks in the last 7 days.".to_string();
+        }
+        let minutes: i64 = blocks
+            .iter()
+            .map(|r| {
+                r.completed_at
+                    .map(|end| (end - r.started_at).num_minutes())
+                    .unwrap_or(r.duration_mins as i64)
+            })
+            .sum();
+        let interruptions: usize = blocks.iter().map(|r| r.interruptions).sum();
+        let held: usize = blocks.iter().map(|r| r.held).sum();
+        let mut text = format!(
+            "Focus report (last 7 days): {} block(s), {}h {:02}m focused, \
+             {} interruption(s), {} message(s) held\n",
+            blocks.len(),
+            minutes / 60,
+            minutes % 60,
+            interruptions,
+            held
+        );
+        for r in blocks.iter().rev() {
+            text.push_str(&format!(
+                "  {} — {} ({} min, {} interruption(s))\n",
+                r.started_at
+                    .with_timezone(&chrono::Local)
+                    .format("%a %Y-%m-%d %H:%M"),
+                r.task,
+                r.duration_mins,
+                r.interruptions
+            ));
+        }
+        text
+    }
+}
+
+/// Send a block's digest to the `[focus.digest]` channel. Returns a line
+/// saying where it went.
+pub async fn send_digest(workspace: &Path, digest: &str) -> String {
+    let config = crate::config::load_config(Some(workspace), None).unwrap_or_default();
+    let Some(target) = config.focus.digest else {
+        return "No [focus.digest] channel configured.".to_string();
+    };
+    let mut manager = crate::channels::build_channel_manager(&config.channels.unwrap_or_default());
+    let Some(channel_type) = manager.channel_type(&target.channel) else {
+        return format!("Channel '{}' is not configured.", target.channel);
+    };
+    let sender = ChannelUser::new("rustant", channel_type).with_name("Rustant");
+    manager.enqueue(
+        target.channel.clone(),
+        ChannelMessage::text(channel_type, &target.destination_id, sender, digest)
+            .with_metadata("focus_digest", "true"),
+    );
+    for (name, result) in manager.connect_all().await {
+        if let (true, Err(e)) = (name == target.channel, result) {
+            return format!("Failed to connect channel '{}': {}", target.channel, e);
+        }
+    }
+    let results = manager.flush_outgoing().await;
+    manager.disconnect_all().await;
+    match results.into_iter().find_map(|(_, r)| r.err()) {
+        Some(e) => format!("Failed to send the digest to '{}': {}", target.channel, e),
+        None => format!("Digest sent to '{}'.", target.channel),
+    }
+}
+
+fn sender_name(user: &ChannelUser) -> String {
+    user.display_name.clone().unwrap_or_else(|| user.id.clone())
+}
+
+fn is_allowed(user: &ChannelUser, allow: &[String]) -> bool {
+    allow.iter().any(|a| {
+        a.eq_ignore_ascii_case(&user.id)
+            || user
+                .display_name
+                .as_deref()
+                .is_some_and(|n| a.eq_ignore_ascii_case(n))
+    })
+}
+
+fn clip(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(MAX_HELD_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::{ChannelType, MessageClassifier};
+
+    fn classified(sender: &str, text: &str, priority: MessagePriority) -> ClassifiedMessage {
+        let user = ChannelUser::new(sender, ChannelType::Slack);
+        let msg = ChannelMessage::text(ChannelType::Slack, "C1", user, text);
+        let mut classified = MessageClassifier::new(Default::default()).classify(&msg);
+        classified.priority = priority;
+        classified
+    }
+
+    fn open_state(now: DateTime<Utc>) -> FocusState {
+        let mut state = FocusState::default();
+        assert!(state.begin("refactor", 25, now - Duration::minutes(5)));
+        state
+    }
+
+    #[test]
+    fn test_screen_holds_and_lets_urgent_through() {
+        let now = Utc::now();
+        let config = FocusConfig {
+            allow_senders: vec!["boss".into()],
+            ..Default::default()
+        };
+        let mut state = open_state(now);
+
+        let low = classified("alice", "lunch?", MessagePriority::Normal);
+        assert_eq!(
+            state.screen(&low, "slack", &config, now),
+            FocusDecision::Hold
+        );
+        let urgent = classified("alice", "prod is down", MessagePriority::Urgent);
+        assert_eq!(
+            state.screen(&urgent, "slack", &config, now),
+            FocusDecision::Interrupt(InterruptReason::Urgent)
+        );
+        let boss = classified("Boss", "quick one", MessagePriority::Low);
+        assert_eq!(
+            state.screen(&boss, "slack", &config, now),
+            FocusDecision::Interrupt(InterruptReason::AllowedSender)
+        );
+
+        let block = state.active.as_ref().unwrap();
+        assert_eq!(block.held.len(), 1);
+        assert_eq!(block.interruptions.len(), 2);
+        assert!(block.digest().unwrap().contains("lunch?"));
+    }
+
+    #[test]
+    fn test_screen_delivers_without_block() {
+        let now = Utc::now();
+        let mut state = FocusState::default();
+        let msg = classified("alice", "hi", MessagePriority::Low);
+        assert_eq!(
+            state.screen(&msg, "slack", &FocusConfig::default(), now),
+            FocusDecision::Deliver
+        );
+    }
+
+    #[test]
+    fn test_reconcile_closes_expired_block() {
+        let now = Utc::now();
+        let mut state = open_state(now);
+        assert!(state.reconcile(now).is_none());
+        state.active.as_mut().unwrap().dnd = true;
+
+        let later = now + Duration::minutes(30);
+        let ended = state.reconcile(later).unwrap();
+        assert!(ended.dnd);
+        assert!(state.active.is_none());
+        let record = state.history.last().unwrap();
+        assert_eq!(record.completed_at, Some(ended.ends_at()));
+        assert!(state.weekly_report(later).contains("1 block(s)"));
+    }
+
+    #[test]
+    fn test_reads_pomodoro_state_files() {
+        let json = r#"{
+            "active": {"task": "writing", "started_at": "2026-01-05T09:00:00Z", "duration_mins": 25, "completed": false},
+            "history": [{"task": "old", "started_at": "2026-01-04T09:00:00Z", "duration_mins": 25,
+                         "completed_at": "2026-01-04T09:25:00Z", "completed": true}]
+        }"#;
+        let state: FocusState = serde_json::from_str(json).unwrap();
+        let block = state.active.unwrap();
+        assert_eq!(block.task, "writing");
+        assert!(!block.dnd);
+        assert_eq!(state.history[0].interruptions, 0);
+    }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = Utc::now();
+        let state = open_state(now);
+        state.save(dir.path()).unwrap();
+        let loaded = FocusState::load(dir.path());
+        assert_eq!(loaded.active_at(now).unwrap().task, "refactor");
+        assert!(
+            FocusState::load(&dir.path().join("missing"))
+                .active
+                .is_none()
+        );
+    }
+}
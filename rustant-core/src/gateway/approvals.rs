@@ -0,0 +1,157 @@
+//! Escalation of gateway approvals that time out.
+//!
+//! When an approval's `[safety.approval_timeouts]` policy escalates, the
+//! gateway hands it to the host's [`ApprovalEscalator`], which delivers it
+//! somewhere a person will see it and reports back any decision made there.
+//! [`ChannelEscalator`] sends it to a configured messaging channel and
+//! accepts `/approve <id>` or `/deny <id>` replies.
+
+use crate::approval_timeout::{EscalationTarget, parse_approval_reply};
+use crate::channels::{ChannelMessage, ChannelUser, MessageContent, build_channel_manager};
+use crate::config::ChannelsConfig;
+use async_trait::async_trait;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Delivers escalated approvals and waits for a decision on them.
+#[async_trait]
+pub trait ApprovalEscalator: Send + Sync {
+    /// Send `message` about `approval_id` to `target`, then wait up to
+    /// `window` for a reply. Returns the decision, or `None` if nobody
+    /// answered in time.
+    async fn escalate(
+        &self,
+        approval_id: Uuid,
+        target: &EscalationTarget,
+        message: &str,
+        window: Duration,
+    ) -> Result<Option<bool>, String>;
+}
+
+/// Escalates to a channel from `[channels]` and polls it for replies.
+pub struct ChannelEscalator {
+    channels: ChannelsConfig,
+    poll_interval: Duration,
+}
+
+impl ChannelEscalator {
+    pub fn new(channels: ChannelsConfig) -> Self {
+        Self {
+            channels,
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+#[async_trait]
+impl ApprovalEscalator for ChannelEscalator {
+    async fn escalate(
+        &self,
+        approval_id: Uuid,
+        target: &EscalationTarget,
+        message: &str,
+        window: Duration,
+    ) -> Result<Option<bool>, String> {
+        let mut manager = build_channel_manager(&self.channels);
+        let Some(channel_type) = manager.channel_type(&target.channel) else {
+            return Err(format!("Channel '{}' is not configured", target.channel));
+        };
+        let sender = ChannelUser::new("rustant", channel_type).with_name("Rustant");
+        manager.enqueue(
+            target.channel.clone(),
+            ChannelMessage::text(channel_type, &target.destination_id, sender, message)
+                .with_metadata("approval_id", approval_id.to_string()),
+        );
+        for (name, result) in manager.connect_all().await {
+            if let (true, Err(e)) = (name == target.channel, result) {
+                return Err(format!("Failed to connect '{}': {}", target.channel, e));
+            }
+        }
+        if let Some(e) = manager
+            .flush_outgoing()
+            .await
+            .into_iter()
+            .find_map(|(_, r)| r.err())
+        {
+            manager.disconnect_all().await;
+            return Err(format!("Failed to send to '{}': {}", target.channel, e));
+        }
+
+        let replies = async {
+            loop {
+                tokio::time::sleep(self.poll_interval).await;
+                for (name, result) in manager.poll_all().await {
+                    let (true, Ok(messages)) = (name == target.channel, result) else {
+                        continue;
+                    };
+                    if let Some(approved) = messages
+                        .iter()
+                        .find_map(|m| reply_decision(m, &target.destination_id, approval_id))
+                    {
+                        return approved;
+                    }
+                }
+            }
+        };
+        let decision = tokio::time::timeout(window, replies).await.ok();
+        manager.disconnect_all().await;
+        Ok(decision)
+    }
+}
+
+/// The decision in `message` if it is a reply about `approval_id` in the
+/// conversation the approval was escalated to.
+fn reply_decision(
+    message: &ChannelMessage,
+    destination_id: &str,
+    approval_id: Uuid,
+) -> Option<bool> {
+    if message.channel_id != destination_id {
+        return None;
+    }
+    let text = match &message.content {
+        MessageContent::Text { text } => text.clone(),
+        MessageContent::Command { command, args } => format!("{} {}", command, args.join(" ")),
+        _ => return None,
+    };
+    let (prefix, approved) = parse_approval_reply(&text)?;
+    approval_id
+        .simple()
+        .to_string()
+        .starts_with(&prefix)
+        .then_some(approved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approval_timeout::short_id;
+    use crate::channels::ChannelType;
+
+    fn message(chat: &str, content: MessageContent) -> ChannelMessage {
+        let mut msg = ChannelMessage::text(
+            ChannelType::Telegram,
+            chat,
+            ChannelUser::new("42", ChannelType::Telegram),
+            "",
+        );
+        msg.content = content;
+        msg
+    }
+
+    #[test]
+    fn test_reply_decision() {
+        let id = Uuid::new_v4();
+        let short = short_id(id);
+        let approve = message("123", MessageContent::text(format!("/approve {}", short)));
+        assert_eq!(reply_decision(&approve, "123", id), Some(true));
+        let deny = message("123", MessageContent::command("deny", vec![short]));
+        assert_eq!(reply_decision(&deny, "123", id), Some(false));
+
+        // Other conversations and other approvals are ignored.
+        assert_eq!(reply_decision(&approve, "999", id), None);
+        assert_eq!(reply_decision(&approve, "123", Uuid::new_v4()), None);
+        let chatter = message("123", MessageContent::text("approve it please"));
+        assert_eq!(reply_decision(&chatter, "123", id), None);
+    }
+}
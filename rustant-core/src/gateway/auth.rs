@@ -1,11 +1,63 @@
 //! Gateway authentication.
 
 use super::GatewayConfig;
+use crate::pairing::hex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+/// What an authenticated connection is allowed to do.
+///
+/// Scopes are ordered: each includes everything the ones before it allow.
+/// Configured tokens carry `Read` or `Tasks`; paired devices may also be
+/// limited to `Status`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthScope {
+    /// Status, metrics, and event streaming, without approval requests.
+    Status,
+    /// Everything in `Status`, plus config, approvals, and watching terminals.
+    Read,
+    /// Everything in `Read`, plus submitting and cancelling tasks.
+    Tasks,
+}
+
+impl fmt::Display for AuthScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AuthScope::Status => "status",
+            AuthScope::Read => "read",
+            AuthScope::Tasks => "tasks",
+        })
+    }
+}
+
+impl FromStr for AuthScope {
+    type Err = String;
+
+    /// Parse a scope name; `approvals` is accepted for `read`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "status" => Ok(AuthScope::Status),
+            "read" | "approvals" => Ok(AuthScope::Read),
+            "tasks" => Ok(AuthScope::Tasks),
+            other => Err(format!(
+                "unknown scope '{}' (expected status, approvals or tasks)",
+                other
+            )),
+        }
+    }
+}
 
 /// Token-based authentication for WebSocket connections.
 #[derive(Debug, Clone)]
 pub struct GatewayAuth {
     valid_tokens: Vec<String>,
+    task_tokens: Vec<String>,
 }
 
 impl GatewayAuth {
@@ -13,34 +65,66 @@ impl GatewayAuth {
     pub fn from_config(config: &GatewayConfig) -> Self {
         Self {
             valid_tokens: config.auth_tokens.clone(),
+            task_tokens: config.task_tokens.clone(),
         }
     }
 
-    /// Create a new auth validator with the given tokens.
+    /// Create a new auth validator with the given (read-only) tokens.
     pub fn new(tokens: Vec<String>) -> Self {
         Self {
             valid_tokens: tokens,
+            task_tokens: Vec::new(),
         }
     }
 
+    /// Add tokens that carry the `Tasks` scope.
+    pub fn with_task_tokens(mut self, tokens: Vec<String>) -> Self {
+        self.task_tokens = tokens;
+        self
+    }
+
     /// Validate a token. Returns `true` if the token is valid.
     ///
     /// If no tokens are configured, all tokens are accepted (open mode).
     pub fn validate(&self, token: &str) -> bool {
-        if self.valid_tokens.is_empty() {
-            return true; // open mode: no auth required
+        self.scope(token).is_some()
+    }
+
+    /// The scope granted to a token, or `None` if it is invalid.
+    ///
+    /// Open mode grants every connection the `Tasks` scope.
+    pub fn scope(&self, token: &str) -> Option<AuthScope> {
+        if self.is_open_mode() {
+            return Some(AuthScope::Tasks); // open mode: no auth required
+        }
+        if self.task_tokens.iter().any(|t| t == token) {
+            Some(AuthScope::Tasks)
+        } else if self.valid_tokens.iter().any(|t| t == token) {
+            Some(AuthScope::Read)
+        } else {
+            None
+        }
+    }
+
+    /// A non-secret identifier for the credential `token` presents, shown
+    /// in client listings: `token:` and the first eight hex digits of its
+    /// SHA-256, or `open` in open mode.
+    pub fn token_id(&self, token: &str) -> String {
+        if self.is_open_mode() {
+            return "open".to_string();
         }
-        self.valid_tokens.iter().any(|t| t == token)
+        let digest = hex::encode(Sha256::digest(token.as_bytes()));
+        format!("token:{}", &digest[..8])
     }
 
     /// Number of configured tokens.
     pub fn token_count(&self) -> usize {
-        self.valid_tokens.len()
+        self.valid_tokens.len() + self.task_tokens.len()
     }
 
     /// Whether the gateway is in open mode (no auth required).
     pub fn is_open_mode(&self) -> bool {
-        self.valid_tokens.is_empty()
+        self.valid_tokens.is_empty() && self.task_tokens.is_empty()
     }
 }
 
@@ -81,4 +165,28 @@ mod tests {
         assert!(auth.validate("abc"));
         assert!(!auth.validate("xyz"));
     }
+
+    #[test]
+    fn test_auth_scopes() {
+        let auth = GatewayAuth::new(vec!["viewer".into()]).with_task_tokens(vec!["admin".into()]);
+        assert_eq!(auth.scope("viewer"), Some(AuthScope::Read));
+        assert_eq!(auth.scope("admin"), Some(AuthScope::Tasks));
+        assert_eq!(auth.scope("nope"), None);
+        assert!(AuthScope::Tasks > AuthScope::Read);
+        assert!(AuthScope::Read > AuthScope::Status);
+        assert_eq!("approvals".parse::<AuthScope>(), Ok(AuthScope::Read));
+        assert_eq!(AuthScope::Status.to_string(), "status");
+        assert_eq!(GatewayAuth::new(vec![]).scope(""), Some(AuthScope::Tasks));
+    }
+
+    #[test]
+    fn test_token_id_hides_token() {
+        let auth = GatewayAuth::new(vec!["viewer-secret".into()]);
+        let id = auth.token_id("viewer-secret");
+        assert!(id.starts_with("token:"));
+        assert_eq!(id.len(), "token:".len() + 8);
+        assert!(!id.contains("viewer"));
+        assert_eq!(id, auth.token_id("viewer-secret"));
+        assert_eq!(GatewayAuth::new(vec![]).token_id("anything"), "open");
+    }
 }
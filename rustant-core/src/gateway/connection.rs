@@ -1,16 +1,91 @@
 //! WebSocket connection management.
 
+use super::auth::AuthScope;
+use super::events::EventTopic;
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::SocketAddr;
 use uuid::Uuid;
 
 /// Metadata about a connected WebSocket client.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionInfo {
     pub connection_id: Uuid,
     pub authenticated: bool,
+    /// Scope granted at authentication (meaningless until authenticated).
+    pub scope: AuthScope,
     pub connected_at: DateTime<Utc>,
     pub last_activity: DateTime<Utc>,
+    /// Event topics forwarded to this client (all topics by default).
+    pub topics: BTreeSet<EventTopic>,
+    /// PTY sessions this client is attached to, with the attachment's scope.
+    pub pty_sessions: BTreeMap<Uuid, AuthScope>,
+    /// The paired device whose token authenticated this connection.
+    pub device_id: Option<Uuid>,
+    /// Peer address, when the listener records it.
+    #[serde(default)]
+    pub remote_addr: Option<SocketAddr>,
+    /// Name the client gave when authenticating, e.g. "Laptop dashboard".
+    #[serde(default)]
+    pub client_name: Option<String>,
+    /// Kind of client it declared, e.g. "dashboard" or "telegram-bridge".
+    #[serde(default)]
+    pub client_type: Option<String>,
+    /// Which credential authenticated it: `device:<id>`, `token:<hash
+    /// prefix>` for a configured token, or `open` without any tokens.
+    #[serde(default)]
+    pub token_id: Option<String>,
+    /// Messages received from the client.
+    #[serde(default)]
+    pub messages_received: u64,
+    /// Messages sent to the client, responses and events alike.
+    #[serde(default)]
+    pub messages_sent: u64,
+}
+
+impl ConnectionInfo {
+    /// How the client is shown in listings and audit entries: its declared
+    /// name and type, else its address.
+    pub fn label(&self) -> String {
+        match (&self.client_name, &self.client_type, &self.remote_addr) {
+            (Some(name), Some(kind), _) => format!("{} ({})", name, kind),
+            (Some(name), None, _) => name.clone(),
+            (None, Some(kind), _) => kind.clone(),
+            (None, None, Some(addr)) => format!("unnamed client at {}", addr),
+            (None, None, None) => "unnamed client".to_string(),
+        }
+    }
+}
+
+/// Why a connection closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectReason {
+    /// The client or the gateway closed the socket normally.
+    Closed,
+    /// The client stopped answering keep-alive pings and was reaped.
+    Stale,
+    /// An operator disconnected it, or its device was revoked.
+    Kicked,
+}
+
+impl DisconnectReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DisconnectReason::Closed => "closed",
+            DisconnectReason::Stale => "stale",
+            DisconnectReason::Kicked => "kicked",
+        }
+    }
+}
+
+/// Connections closed since the gateway started, by reason.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisconnectCounts {
+    pub closed: u64,
+    pub stale: u64,
+    pub kicked: u64,
 }
 
 /// Manages active WebSocket connections.
@@ -18,6 +93,7 @@ pub struct ConnectionInfo {
 pub struct ConnectionManager {
     connections: HashMap<Uuid, ConnectionInfo>,
     max_connections: usize,
+    disconnects: DisconnectCounts,
 }
 
 impl ConnectionManager {
@@ -26,11 +102,24 @@ impl ConnectionManager {
         Self {
             connections: HashMap::new(),
             max_connections,
+            disconnects: DisconnectCounts::default(),
         }
     }
 
+    /// Change the connection limit. Existing connections above a lowered
+    /// limit stay open; new ones are refused until enough have closed.
+    pub fn set_max_connections(&mut self, max_connections: usize) {
+        self.max_connections = max_connections;
+    }
+
     /// Register a new connection. Returns `None` if the limit is reached.
     pub fn add_connection(&mut self) -> Option<Uuid> {
+        self.add_connection_from(None)
+    }
+
+    /// Register a new connection from `remote_addr`. Returns `None` if the
+    /// limit is reached.
+    pub fn add_connection_from(&mut self, remote_addr: Option<SocketAddr>) -> Option<Uuid> {
         if self.connections.len() >= self.max_connections {
             return None;
         }
@@ -42,22 +131,84 @@ impl ConnectionManager {
             ConnectionInfo {
                 connection_id: id,
                 authenticated: false,
+                scope: AuthScope::Read,
                 connected_at: now,
                 last_activity: now,
+                topics: EventTopic::ALL.into_iter().collect(),
+                pty_sessions: BTreeMap::new(),
+                device_id: None,
+                remote_addr,
+                client_name: None,
+                client_type: None,
+                token_id: None,
+                messages_received: 0,
+                messages_sent: 0,
             },
         );
         Some(id)
     }
 
-    /// Remove a connection.
+    /// Remove a connection that closed normally.
     pub fn remove_connection(&mut self, id: &Uuid) -> bool {
-        self.connections.remove(id).is_some()
+        self.remove_connection_with(id, DisconnectReason::Closed)
+            .is_some()
+    }
+
+    /// Remove a connection, counting it under `reason`. Returns its final
+    /// metadata.
+    pub fn remove_connection_with(
+        &mut self,
+        id: &Uuid,
+        reason: DisconnectReason,
+    ) -> Option<ConnectionInfo> {
+        let info = self.connections.remove(id)?;
+        match reason {
+            DisconnectReason::Closed => self.disconnects.closed += 1,
+            DisconnectReason::Stale => self.disconnects.stale += 1,
+            DisconnectReason::Kicked => self.disconnects.kicked += 1,
+        }
+        crate::metrics::record_gateway_disconnect(reason.as_str());
+        Some(info)
+    }
+
+    /// Connections closed so far, by reason.
+    pub fn disconnect_counts(&self) -> DisconnectCounts {
+        self.disconnects
+    }
+
+    /// Record the name and type a client declared and the credential it
+    /// authenticated with. Blank values are ignored.
+    pub fn identify(
+        &mut self,
+        id: &Uuid,
+        client_name: Option<String>,
+        client_type: Option<String>,
+        token_id: String,
+    ) {
+        let clean = |value: Option<String>| {
+            value
+                .map(|v| v.trim().chars().take(64).collect::<String>())
+                .filter(|v| !v.is_empty())
+        };
+        if let Some(conn) = self.connections.get_mut(id) {
+            conn.client_name = clean(client_name);
+            conn.client_type = clean(client_type);
+            conn.token_id = Some(token_id);
+        }
     }
 
-    /// Mark a connection as authenticated.
+    /// Mark a connection as authenticated with the full (`Tasks`) scope.
     pub fn authenticate(&mut self, id: &Uuid) -> bool {
+        self.authenticate_with_scope(id, AuthScope::Tasks)
+    }
+
+    /// Mark a connection as authenticated with the given scope.
+    pub fn authenticate_with_scope(&mut self, id: &Uuid, scope: AuthScope) -> bool {
         if let Some(conn) = self.connections.get_mut(id) {
             conn.authenticated = true;
+            conn.scope = scope;
+            conn.device_id = None;
+            conn.topics.retain(|t| topic_allowed(*t, scope));
             conn.last_activity = Utc::now();
             true
         } else {
@@ -65,6 +216,29 @@ impl ConnectionManager {
         }
     }
 
+    /// Mark a connection as authenticated by a paired device's token.
+    pub fn authenticate_device(&mut self, id: &Uuid, scope: AuthScope, device_id: Uuid) -> bool {
+        let authenticated = self.authenticate_with_scope(id, scope);
+        if let Some(conn) = self.connections.get_mut(id) {
+            conn.device_id = Some(device_id);
+        }
+        authenticated
+    }
+
+    /// Revoke authentication of every connection opened by `device_id`,
+    /// returning their IDs.
+    pub fn deauthenticate_device(&mut self, device_id: &Uuid) -> Vec<Uuid> {
+        let mut ids = Vec::new();
+        for conn in self.connections.values_mut() {
+            if conn.device_id.as_ref() == Some(device_id) {
+                conn.authenticated = false;
+                conn.pty_sessions.clear();
+                ids.push(conn.connection_id);
+            }
+        }
+        ids
+    }
+
     /// Update the last activity timestamp for a connection.
     pub fn touch(&mut self, id: &Uuid) {
         if let Some(conn) = self.connections.get_mut(id) {
@@ -72,11 +246,40 @@ impl ConnectionManager {
         }
     }
 
+    /// Count a message received from a connection and update its activity.
+    pub fn record_received(&mut self, id: &Uuid) {
+        if let Some(conn) = self.connections.get_mut(id) {
+            conn.messages_received += 1;
+            conn.last_activity = Utc::now();
+        }
+    }
+
+    /// Count `count` messages sent to a connection.
+    pub fn record_sent(&mut self, id: &Uuid, count: u64) {
+        if let Some(conn) = self.connections.get_mut(id) {
+            conn.messages_sent += count;
+        }
+    }
+
+    /// Whether a connection has shown no activity for longer than `idle`.
+    pub fn is_idle(&self, id: &Uuid, idle: chrono::Duration) -> bool {
+        self.connections
+            .get(id)
+            .is_some_and(|c| Utc::now() - c.last_activity > idle)
+    }
+
     /// Get connection info.
     pub fn get(&self, id: &Uuid) -> Option<&ConnectionInfo> {
         self.connections.get(id)
     }
 
+    /// All connections, oldest first.
+    pub fn list(&self) -> Vec<&ConnectionInfo> {
+        let mut connections: Vec<_> = self.connections.values().collect();
+        connections.sort_by_key(|c| c.connected_at);
+        connections
+    }
+
     /// Number of active connections.
     pub fn active_count(&self) -> usize {
         self.connections.len()
@@ -95,6 +298,75 @@ impl ConnectionManager {
         self.connections.keys().copied().collect()
     }
 
+    /// Check if a connection is authenticated with at least `scope`.
+    pub fn has_scope(&self, id: &Uuid, scope: AuthScope) -> bool {
+        self.connections
+            .get(id)
+            .is_some_and(|c| c.authenticated && c.scope >= scope)
+    }
+
+    /// Add `topics` to a connection's subscription. Returns the resulting
+    /// topics, or `None` for an unknown connection.
+    pub fn subscribe(&mut self, id: &Uuid, topics: &[EventTopic]) -> Option<Vec<EventTopic>> {
+        let conn = self.connections.get_mut(id)?;
+        let scope = conn.scope;
+        conn.topics.extend(
+            topics
+                .iter()
+                .copied()
+                .filter(|t| !conn.authenticated || topic_allowed(*t, scope)),
+        );
+        Some(conn.topics.iter().copied().collect())
+    }
+
+    /// Remove `topics` from a connection's subscription. Returns the
+    /// resulting topics, or `None` for an unknown connection.
+    pub fn unsubscribe(&mut self, id: &Uuid, topics: &[EventTopic]) -> Option<Vec<EventTopic>> {
+        let conn = self.connections.get_mut(id)?;
+        conn.topics.retain(|t| !topics.contains(t));
+        Some(conn.topics.iter().copied().collect())
+    }
+
+    /// Topics a connection is subscribed to (empty for unknown connections).
+    pub fn topics(&self, id: &Uuid) -> BTreeSet<EventTopic> {
+        self.connections
+            .get(id)
+            .map(|c| c.topics.clone())
+            .unwrap_or_default()
+    }
+
+    /// Attach a connection to a PTY session with `scope`.
+    pub fn attach_pty(&mut self, id: &Uuid, session_id: Uuid, scope: AuthScope) -> bool {
+        if let Some(conn) = self.connections.get_mut(id) {
+            conn.pty_sessions.insert(session_id, scope);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Detach a connection from a PTY session. Returns whether it was attached.
+    pub fn detach_pty(&mut self, id: &Uuid, session_id: &Uuid) -> bool {
+        self.connections
+            .get_mut(id)
+            .is_some_and(|c| c.pty_sessions.remove(session_id).is_some())
+    }
+
+    /// Scope of a connection's attachment to a PTY session, if attached.
+    pub fn pty_scope(&self, id: &Uuid, session_id: &Uuid) -> Option<AuthScope> {
+        self.connections
+            .get(id)
+            .and_then(|c| c.pty_sessions.get(session_id).copied())
+    }
+
+    /// PTY sessions a connection is attached to (empty for unknown connections).
+    pub fn pty_sessions(&self, id: &Uuid) -> BTreeSet<Uuid> {
+        self.connections
+            .get(id)
+            .map(|c| c.pty_sessions.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
     /// Check if a connection is authenticated.
     pub fn is_authenticated(&self, id: &Uuid) -> bool {
         self.connections
@@ -104,6 +376,11 @@ impl ConnectionManager {
     }
 }
 
+/// Approval requests are withheld from `status`-only connections.
+fn topic_allowed(topic: EventTopic, scope: AuthScope) -> bool {
+    topic != EventTopic::Approvals || scope >= AuthScope::Read
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,4 +469,104 @@ mod tests {
         let updated = mgr.get(&id).unwrap().last_activity;
         assert!(updated >= initial);
     }
+
+    #[test]
+    fn test_subscriptions_default_to_all_topics() {
+        let mut mgr = ConnectionManager::new(10);
+        let id = mgr.add_connection().unwrap();
+        assert_eq!(mgr.topics(&id).len(), EventTopic::ALL.len());
+
+        let remaining = mgr
+            .unsubscribe(&id, &[EventTopic::Progress, EventTopic::Channels])
+            .unwrap();
+        assert!(!remaining.contains(&EventTopic::Progress));
+        assert_eq!(remaining.len(), EventTopic::ALL.len() - 2);
+
+        let topics = mgr.unsubscribe(&id, &EventTopic::ALL).unwrap();
+        assert!(topics.is_empty());
+        let topics = mgr.subscribe(&id, &[EventTopic::Approvals]).unwrap();
+        assert_eq!(topics, vec![EventTopic::Approvals]);
+        assert!(
+            mgr.subscribe(&Uuid::new_v4(), &[EventTopic::Metrics])
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_device_connections() {
+        let mut mgr = ConnectionManager::new(10);
+        let phone = mgr.add_connection().unwrap();
+        let desktop = mgr.add_connection().unwrap();
+        let device_id = Uuid::new_v4();
+        assert!(mgr.authenticate_device(&phone, AuthScope::Status, device_id));
+        assert!(mgr.authenticate(&desktop));
+
+        // Status-only devices never see approval requests.
+        assert!(!mgr.topics(&phone).contains(&EventTopic::Approvals));
+        let topics = mgr.subscribe(&phone, &[EventTopic::Approvals]).unwrap();
+        assert!(!topics.contains(&EventTopic::Approvals));
+
+        assert_eq!(mgr.deauthenticate_device(&device_id), vec![phone]);
+        assert!(!mgr.is_authenticated(&phone));
+        assert!(mgr.is_authenticated(&desktop));
+    }
+
+    #[test]
+    fn test_client_metadata_and_activity() {
+        let mut mgr = ConnectionManager::new(10);
+        let addr: SocketAddr = "192.168.1.7:50512".parse().unwrap();
+        let id = mgr.add_connection_from(Some(addr)).unwrap();
+        assert_eq!(
+            mgr.get(&id).unwrap().label(),
+            "unnamed client at 192.168.1.7:50512"
+        );
+
+        mgr.identify(
+            &id,
+            Some(" Laptop dashboard ".into()),
+            Some("".into()),
+            "token:3f9a1c2e".into(),
+        );
+        mgr.record_received(&id);
+        mgr.record_received(&id);
+        mgr.record_sent(&id, 5);
+        let info = mgr.get(&id).unwrap();
+        assert_eq!(info.label(), "Laptop dashboard");
+        assert!(info.client_type.is_none());
+        assert_eq!(info.token_id.as_deref(), Some("token:3f9a1c2e"));
+        assert_eq!((info.messages_received, info.messages_sent), (2, 5));
+        assert!(!mgr.is_idle(&id, chrono::Duration::seconds(60)));
+        assert!(mgr.is_idle(&id, chrono::Duration::seconds(-1)));
+    }
+
+    #[test]
+    fn test_disconnect_counts_by_reason() {
+        let mut mgr = ConnectionManager::new(10);
+        let first = mgr.add_connection().unwrap();
+        let second = mgr.add_connection().unwrap();
+        let third = mgr.add_connection().unwrap();
+        assert_eq!(mgr.list().first().unwrap().connection_id, first);
+
+        assert!(mgr.remove_connection(&first));
+        assert!(
+            mgr.remove_connection_with(&second, DisconnectReason::Stale)
+                .is_some()
+        );
+        assert!(
+            mgr.remove_connection_with(&third, DisconnectReason::Kicked)
+                .is_some()
+        );
+        assert!(
+            mgr.remove_connection_with(&third, DisconnectReason::Kicked)
+                .is_none()
+        );
+        assert_eq!(
+            mgr.disconnect_counts(),
+            DisconnectCounts {
+                closed: 1,
+                stale: 1,
+                kicked: 1
+            }
+        );
+    }
 }
@@ -0,0 +1,493 @@
+//! Devices paired with the gateway by QR code.
+//!
+//! An operator asks for a [`PairingOffer`] with `rustant gateway pair` or the
+//! dashboard's *Pair device* button. The offer wraps a single-use
+//! [`PairingChallenge`] and a one-time secret in a URL, shown as a QR code.
+//! The joining device opens the URL, answers with
+//! `HMAC-SHA256(secret, nonce)` and receives a device token carrying the
+//! scope picked when the offer was made. Device tokens authenticate like
+//! configured gateway tokens, but only their SHA-256 hashes are stored, and
+//! each device can be revoked on its own.
+
+use super::auth::AuthScope;
+use crate::pairing::{DeviceIdentity, PairingChallenge, PairingResponse, compute_hmac, hex};
+use chrono::{Duration, Utc};
+use qrcode::{Color, QrCode};
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// How long a pairing QR code stays valid by default.
+pub const DEFAULT_CHALLENGE_TTL_SECS: u64 = 120;
+/// Prefix of device tokens, so they are recognisable in logs and configs.
+const TOKEN_PREFIX: &str = "rdt_";
+/// `last_seen` is written back to disk at most this often per device.
+const LAST_SEEN_PERSIST_SECS: i64 = 300;
+/// Longest accepted device name or platform.
+const MAX_LABEL_CHARS: usize = 64;
+
+/// Errors from device pairing.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PairingError {
+    #[error("Unknown or already used pairing code")]
+    UnknownChallenge,
+    #[error("Pairing code expired; ask for a new one")]
+    Expired,
+    #[error("Pairing response does not match the challenge")]
+    Rejected,
+    #[error("Invalid device: {0}")]
+    InvalidDevice(String),
+    #[error("Failed to encode QR code: {0}")]
+    Qr(String),
+    #[error("Device store failed: {0}")]
+    Store(String),
+}
+
+/// A pairing invitation, shown to the joining device as a QR code.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PairingOffer {
+    pub challenge: PairingChallenge,
+    /// One-time HMAC key the device proves it holds. Also part of `url`.
+    pub secret: String,
+    /// Scope the device receives once paired.
+    pub scope: AuthScope,
+    /// Pairing page URL encoded in the QR code. The challenge travels in the
+    /// fragment, so it never reaches server logs.
+    pub url: String,
+}
+
+/// A device's answer to a [`PairingOffer`], posted by the pairing page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevicePairingRequest {
+    #[serde(flatten)]
+    pub response: PairingResponse,
+    /// e.g. "iPhone" or "Android"; informational only.
+    #[serde(default)]
+    pub platform: String,
+}
+
+/// Credentials issued to a device that completed pairing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceGrant {
+    pub device_id: Uuid,
+    /// Gateway token for `Authenticate`. Shown once; only its hash is kept.
+    pub token: String,
+    pub scope: AuthScope,
+}
+
+/// A paired device as listed to operators.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PairedDevice {
+    #[serde(flatten)]
+    pub identity: DeviceIdentity,
+    #[serde(default)]
+    pub platform: String,
+    pub scope: AuthScope,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredDevice {
+    #[serde(flatten)]
+    device: PairedDevice,
+    token_hash: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DeviceFile {
+    devices: Vec<StoredDevice>,
+}
+
+#[derive(Debug, Clone)]
+struct PendingPairing {
+    challenge: PairingChallenge,
+    secret: String,
+    scope: AuthScope,
+}
+
+/// Paired devices and outstanding pairing offers.
+#[derive(Debug, Default)]
+pub struct DeviceRegistry {
+    /// Where devices are persisted; in memory only when `None`.
+    path: Option<PathBuf>,
+    devices: Vec<StoredDevice>,
+    pending: Vec<PendingPairing>,
+}
+
+impl DeviceRegistry {
+    /// An in-memory registry with no devices.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load devices from `path` (missing is fine) and persist changes there.
+    pub fn open(path: PathBuf) -> Result<Self, PairingError> {
+        let devices = match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                serde_json::from_str::<DeviceFile>(&text)
+                    .map_err(|e| PairingError::Store(format!("{}: {}", path.display(), e)))?
+                    .devices
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(PairingError::Store(format!("{}: {}", path.display(), e))),
+        };
+        Ok(Self {
+            path: Some(path),
+            devices,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Issue a single-use offer granting `scope`, valid for `ttl_secs`.
+    /// `base_url` is where the joining device reaches the gateway.
+    pub fn create_offer(
+        &mut self,
+        scope: AuthScope,
+        ttl_secs: u64,
+        base_url: &str,
+    ) -> PairingOffer {
+        self.cleanup_expired();
+        let mut rng = rand::thread_rng();
+        let nonce: [u8; 32] = rng.r#gen();
+        let secret: [u8; 32] = rng.r#gen();
+        let challenge = PairingChallenge {
+            challenge_id: Uuid::new_v4(),
+            nonce: hex::encode(nonce),
+            expires_at: Utc::now() + Duration::seconds(ttl_secs.max(1) as i64),
+        };
+        let secret = hex::encode(secret);
+        let url = format!(
+            "{}/pair#c={}&n={}&k={}&s={}",
+            base_url.trim_end_matches('/'),
+            challenge.challenge_id,
+            challenge.nonce,
+            secret,
+            scope
+        );
+        self.pending.push(PendingPairing {
+            challenge: challenge.clone(),
+            secret: secret.clone(),
+            scope,
+        });
+        PairingOffer {
+            challenge,
+            secret,
+            scope,
+            url,
+        }
+    }
+
+    /// Check a device's response and register it. The challenge is consumed
+    /// whether or not the response is accepted.
+    pub fn complete(
+        &mut self,
+        request: &DevicePairingRequest,
+    ) -> Result<(PairedDevice, DeviceGrant), PairingError> {
+        let response = &request.response;
+        let idx = self
+            .pending
+            .iter()
+            .position(|p| p.challenge.challenge_id == response.challenge_id)
+            .ok_or(PairingError::UnknownChallenge)?;
+        let pending = self.pending.remove(idx);
+        if Utc::now() > pending.challenge.expires_at {
+            return Err(PairingError::Expired);
+        }
+        let expected = compute_hmac(
+            pending.secret.as_bytes(),
+            pending.challenge.nonce.as_bytes(),
+        );
+        if !constant_time_eq(&expected, response.response_hmac.trim()) {
+            return Err(PairingError::Rejected);
+        }
+        let device_name = clean_label(&response.device_name);
+        if device_name.is_empty() {
+            return Err(PairingError::InvalidDevice("name is required".into()));
+        }
+
+        let token = format!(
+            "{}{}",
+            TOKEN_PREFIX,
+            hex::encode(rand::thread_rng().r#gen::<[u8; 32]>())
+        );
+        let now = Utc::now();
+        let device = PairedDevice {
+            identity: DeviceIdentity {
+                device_id: response.device_id,
+                device_name,
+                public_key: response.public_key.clone(),
+                created_at: now,
+                last_seen: now,
+            },
+            platform: clean_label(&request.platform),
+            scope: pending.scope,
+        };
+        // Pairing again replaces the device's previous token.
+        self.devices
+            .retain(|d| d.device.identity.device_id != response.device_id);
+        self.devices.push(StoredDevice {
+            device: device.clone(),
+            token_hash: hash_token(&token),
+        });
+        self.persist()?;
+
+        let grant = DeviceGrant {
+            device_id: response.device_id,
+            token,
+            scope: pending.scope,
+        };
+        Ok((device, grant))
+    }
+
+    /// The device and scope a token belongs to, refreshing its `last_seen`.
+    pub fn authenticate(&mut self, token: &str) -> Option<(Uuid, AuthScope)> {
+        if !token.starts_with(TOKEN_PREFIX) {
+            return None;
+        }
+        let hash = hash_token(token);
+        let stored = self
+            .devices
+            .iter_mut()
+            .find(|d| constant_time_eq(&d.token_hash, &hash))?;
+        let now = Utc::now();
+        let stale =
+            now - stored.device.identity.last_seen > Duration::seconds(LAST_SEEN_PERSIST_SECS);
+        stored.device.identity.last_seen = now;
+        let grant = (stored.device.identity.device_id, stored.device.scope);
+        if stale && let Err(e) = self.persist() {
+            tracing::warn!("Failed to record device last-seen time: {}", e);
+        }
+        Some(grant)
+    }
+
+    /// All paired devices, oldest first.
+    pub fn devices(&self) -> Vec<PairedDevice> {
+        self.devices.iter().map(|d| d.device.clone()).collect()
+    }
+
+    /// Remove a device, invalidating its token. Returns the removed device.
+    pub fn revoke(&mut self, device_id: &Uuid) -> Result<Option<PairedDevice>, PairingError> {
+        let Some(idx) = self
+            .devices
+            .iter()
+            .position(|d| d.device.identity.device_id == *device_id)
+        else {
+            return Ok(None);
+        };
+        let removed = self.devices.remove(idx);
+        self.persist()?;
+        Ok(Some(removed.device))
+    }
+
+    /// Offers not yet used or expired.
+    pub fn pending_count(&mut self) -> usize {
+        self.cleanup_expired();
+        self.pending.len()
+    }
+
+    fn cleanup_expired(&mut self) {
+        let now = Utc::now();
+        self.pending.retain(|p| p.challenge.expires_at > now);
+    }
+
+    /// Write the device list, readable by the owner only.
+    fn persist(&self) -> Result<(), PairingError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let file = DeviceFile {
+            devices: self.devices.clone(),
+        };
+        let json =
+            serde_json::to_vec_pretty(&file).map_err(|e| PairingError::Store(e.to_string()))?;
+        write_private(path, &json)
+            .map_err(|e| PairingError::Store(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// Write `data` to `path` atomically with owner-only permissions.
+fn write_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+/// Trim a device-supplied label and drop control characters.
+fn clean_label(label: &str) -> String {
+    label
+        .trim()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_LABEL_CHARS)
+        .collect()
+}
+
+/// Render `data` as a QR code for a terminal, two modules per character row.
+pub fn qr_terminal(data: &str) -> Result<String, PairingError> {
+    use qrcode::render::unicode::Dense1x2;
+    let code = QrCode::new(data.as_bytes()).map_err(|e| PairingError::Qr(e.to_string()))?;
+    // Light-on-dark terminals read inverted codes poorly, so draw dark
+    // modules as spaces on a light (block) background.
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .quiet_zone(true)
+        .build())
+}
+
+/// Render `data` as a grayscale PNG QR code, `scale` pixels per module.
+pub fn qr_png(data: &str, scale: u32) -> Result<Vec<u8>, PairingError> {
+    const QUIET_ZONE: u32 = 4;
+    let code = QrCode::new(data.as_bytes()).map_err(|e| PairingError::Qr(e.to_string()))?;
+    let width = code.width() as u32;
+    let modules = code.to_colors();
+    let scale = scale.max(1);
+    let size = (width + 2 * QUIET_ZONE) * scale;
+
+    let mut pixels = Vec::with_capacity((size * size) as usize);
+    for y in 0..size {
+        let my = (y / scale).checked_sub(QUIET_ZONE);
+        for x in 0..size {
+            let mx = (x / scale).checked_sub(QUIET_ZONE);
+            let dark = match (mx, my) {
+                (Some(mx), Some(my)) if mx < width && my < width => {
+                    modules[(my * width + mx) as usize] == Color::Dark
+                }
+                _ => false,
+            };
+            pixels.push(if dark { 0 } else { 255 });
+        }
+    }
+    crate::canvas::export::encode_png(size, size, 1, &pixels)
+        .map_err(|e| PairingError::Qr(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(offer: &PairingOffer, name: &str) -> DevicePairingRequest {
+        DevicePairingRequest {
+            response: PairingResponse {
+                challenge_id: offer.challenge.challenge_id,
+                device_id: Uuid::new_v4(),
+                device_name: name.into(),
+                public_key: String::new(),
+                response_hmac: compute_hmac(
+                    offer.secret.as_bytes(),
+                    offer.challenge.nonce.as_bytes(),
+                ),
+            },
+            platform: "iPhone".into(),
+        }
+    }
+
+    #[test]
+    fn test_pairing_issues_a_scoped_token_once() {
+        let mut registry = DeviceRegistry::new();
+        let offer = registry.create_offer(AuthScope::Read, 120, "http://phone.example:18790/");
+        assert!(offer.url.starts_with("http://phone.example:18790/pair#c="));
+        assert!(offer.url.ends_with("&s=read"));
+
+        let request = answer(&offer, "  Dev's phone\n");
+        let (device, grant) = registry.complete(&request).unwrap();
+        assert_eq!(device.identity.device_name, "Dev's phone");
+        assert_eq!(grant.scope, AuthScope::Read);
+        assert_eq!(
+            registry.authenticate(&grant.token),
+            Some((grant.device_id, AuthScope::Read))
+        );
+        assert_eq!(registry.authenticate("rdt_forged"), None);
+
+        // Single use.
+        assert_eq!(
+            registry.complete(&request).unwrap_err(),
+            PairingError::UnknownChallenge
+        );
+    }
+
+    #[test]
+    fn test_wrong_or_late_responses_are_rejected() {
+        let mut registry = DeviceRegistry::new();
+        let offer = registry.create_offer(AuthScope::Tasks, 120, "http://gw");
+        let mut request = answer(&offer, "phone");
+        request.response.response_hmac = "00".repeat(32);
+        assert_eq!(
+            registry.complete(&request).unwrap_err(),
+            PairingError::Rejected
+        );
+        // A failed attempt still consumes the challenge.
+        assert_eq!(registry.pending_count(), 0);
+
+        let offer = registry.create_offer(AuthScope::Tasks, 120, "http://gw");
+        registry.pending[0].challenge.expires_at = Utc::now() - Duration::seconds(1);
+        assert_eq!(
+            registry.complete(&answer(&offer, "phone")).unwrap_err(),
+            PairingError::Expired
+        );
+        assert!(registry.devices().is_empty());
+    }
+
+    #[test]
+    fn test_devices_persist_and_revoke() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("devices.json");
+        let mut registry = DeviceRegistry::open(path.clone()).unwrap();
+        let offer = registry.create_offer(AuthScope::Status, 120, "http://gw");
+        let (device, grant) = registry.complete(&answer(&offer, "tablet")).unwrap();
+        let stored = std::fs::read_to_string(&path).unwrap();
+        assert!(!stored.contains(&grant.token), "only the hash is stored");
+
+        let mut reopened = DeviceRegistry::open(path.clone()).unwrap();
+        assert_eq!(reopened.devices().len(), 1);
+        assert_eq!(reopened.devices()[0].platform, "iPhone");
+        assert!(reopened.authenticate(&grant.token).is_some());
+
+        let revoked = reopened.revoke(&device.identity.device_id).unwrap();
+        assert_eq!(revoked.unwrap().identity.device_name, "tablet");
+        assert!(reopened.authenticate(&grant.token).is_none());
+        assert!(DeviceRegistry::open(path).unwrap().devices().is_empty());
+    }
+
+    #[test]
+    fn test_qr_renderers() {
+        let url = "http://gw/pair#c=1&n=2&k=3&s=status";
+        let text = qr_terminal(url).unwrap();
+        assert!(text.lines().count() > 10);
+
+        let png = qr_png(url, 4).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(&png[12..16], b"IHDR");
+        let side = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        assert_eq!(side % 4, 0);
+        assert!(png.ends_with(&[0xae, 0x42, 0x60, 0x82]), "IEND CRC");
+    }
+}
@@ -0,0 +1,264 @@
+//! Bounded per-connection event queue with topic filtering.
+//!
+//! Each WebSocket connection drains the broadcast channel into its own
+//! [`EventQueue`] right away, so events a client did not subscribe to never
+//! occupy broadcast capacity. When a client reads too slowly and its queue
+//! fills, the oldest `progress` event is dropped first and approval requests
+//! are never dropped; the client is told how many events it lost. PTY
+//! output is only queued for the sessions the connection is attached to.
+
+use super::events::{EventTopic, GatewayEvent, ServerMessage};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::Mutex;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+#[derive(Debug, Default)]
+struct QueueState {
+    topics: BTreeSet<EventTopic>,
+    /// PTY sessions whose output is forwarded.
+    pty_sessions: BTreeSet<Uuid>,
+    events: VecDeque<GatewayEvent>,
+    /// Dropped event counts per topic since the last drain.
+    dropped: BTreeMap<EventTopic, u64>,
+    /// Events lost before topic filtering (broadcast lag).
+    lagged: u64,
+}
+
+/// Events waiting to be written to one connection.
+#[derive(Debug)]
+pub struct EventQueue {
+    capacity: usize,
+    state: Mutex<QueueState>,
+    notify: Notify,
+}
+
+impl EventQueue {
+    /// A queue holding up to `capacity` events, subscribed to every topic.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(QueueState {
+                topics: EventTopic::ALL.into_iter().collect(),
+                ..Default::default()
+            }),
+            notify: Notify::new(),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replace the topics events are filtered by.
+    pub fn set_topics(&self, topics: BTreeSet<EventTopic>) {
+        self.state().topics = topics;
+    }
+
+    /// Replace the PTY sessions whose output is forwarded.
+    pub fn set_pty_sessions(&self, sessions: BTreeSet<Uuid>) {
+        self.state().pty_sessions = sessions;
+    }
+
+    /// Queue `event` if its topic is subscribed. Returns whether it was
+    /// queued; a full queue evicts the oldest progress event, then the
+    /// oldest other non-approval event, and only grows past capacity for
+    /// approval requests.
+    pub fn push(&self, event: GatewayEvent) -> bool {
+        let topic = event.topic();
+        let mut state = self.state();
+        if !state.topics.contains(&topic) {
+            return false;
+        }
+        if let GatewayEvent::PtyOutput { session_id, .. } = &event
+            && !state.pty_sessions.contains(session_id)
+        {
+            return false;
+        }
+        if state.events.len() >= self.capacity {
+            let victim = state
+                .events
+                .iter()
+                .position(|e| e.topic() == EventTopic::Progress)
+                .or_else(|| {
+                    // Progress never evicts anything but other progress.
+                    (topic != EventTopic::Progress)
+                        .then(|| {
+                            state
+                                .events
+                                .iter()
+                                .position(|e| e.topic() != EventTopic::Approvals)
+                        })
+                        .flatten()
+                });
+            match victim {
+                Some(index) => {
+                    if let Some(evicted) = state.events.remove(index) {
+                        *state.dropped.entry(evicted.topic()).or_default() += 1;
+                    }
+                }
+                None if topic == EventTopic::Approvals => {}
+                None => {
+                    *state.dropped.entry(topic).or_default() += 1;
+                    return false;
+                }
+            }
+        }
+        state.events.push_back(event);
+        drop(state);
+        self.notify.notify_one();
+        true
+    }
+
+    /// Record `count` events lost to broadcast lag before they were filtered.
+    pub fn record_lag(&self, count: u64) {
+        self.state().lagged += count;
+        self.notify.notify_one();
+    }
+
+    /// Wait until events or a drop report are ready.
+    pub async fn ready(&self) {
+        self.notify.notified().await
+    }
+
+    /// Take everything queued as server messages, preceded by an
+    /// [`ServerMessage::EventsDropped`] report when events were lost.
+    pub fn drain(&self) -> Vec<ServerMessage> {
+        let mut state = self.state();
+        let mut messages = Vec::with_capacity(state.events.len() + 1);
+        let dropped: u64 = state.dropped.values().sum::<u64>() + state.lagged;
+        if dropped > 0 {
+            messages.push(ServerMessage::EventsDropped {
+                count: dropped,
+                topics: std::mem::take(&mut state.dropped).into_keys().collect(),
+            });
+            state.lagged = 0;
+        }
+        messages.extend(
+            state
+                .events
+                .drain(..)
+                .map(|event| ServerMessage::Event { event }),
+        );
+        messages
+    }
+
+    /// Number of queued events.
+    pub fn len(&self) -> usize {
+        self.state().events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(n: u32) -> GatewayEvent {
+        GatewayEvent::TaskProgress {
+            task_id: Uuid::nil(),
+            progress: n as f32,
+            message: format!("step {}", n),
+        }
+    }
+
+    fn approval() -> GatewayEvent {
+        GatewayEvent::ApprovalRequest {
+            approval_id: Uuid::new_v4(),
+            tool_name: "shell_exec".into(),
+            description: "rm".into(),
+            risk_level: "high".into(),
+            expires_at: None,
+        }
+    }
+
+    fn metrics() -> GatewayEvent {
+        GatewayEvent::ConfigSnapshot {
+            config_json: "{}".into(),
+        }
+    }
+
+    #[test]
+    fn test_filters_by_subscribed_topics() {
+        let queue = EventQueue::new(8);
+        assert!(queue.push(progress(1)));
+        queue.set_topics([EventTopic::Approvals].into_iter().collect());
+        assert!(!queue.push(progress(2)));
+        assert!(queue.push(approval()));
+        assert_eq!(queue.len(), 2);
+        // Unsubscribed events are filtered, not dropped.
+        assert!(
+            !queue
+                .drain()
+                .iter()
+                .any(|m| matches!(m, ServerMessage::EventsDropped { .. }))
+        );
+    }
+
+    #[test]
+    fn test_pty_output_only_for_attached_sessions() {
+        let queue = EventQueue::new(8);
+        let attached = Uuid::new_v4();
+        let output = |session_id| GatewayEvent::PtyOutput {
+            session_id,
+            data: "$ ".into(),
+        };
+        assert!(!queue.push(output(attached)));
+        queue.set_pty_sessions([attached].into_iter().collect());
+        assert!(queue.push(output(attached)));
+        assert!(!queue.push(output(Uuid::new_v4())));
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn test_overflow_drops_oldest_progress_but_never_approvals() {
+        let queue = EventQueue::new(3);
+        queue.push(approval());
+        queue.push(progress(1));
+        queue.push(progress(2));
+        queue.push(progress(3)); // evicts step 1
+        queue.push(approval()); // evicts step 2
+        queue.push(metrics()); // evicts step 3
+        assert!(!queue.push(progress(4))); // nothing but progress may go
+        queue.push(approval()); // evicts the metrics snapshot
+        queue.push(approval()); // only approvals left: grows past capacity
+        assert_eq!(queue.len(), 4);
+
+        let messages = queue.drain();
+        match &messages[0] {
+            ServerMessage::EventsDropped { count, topics } => {
+                assert_eq!(*count, 5);
+                assert_eq!(topics, &vec![EventTopic::Metrics, EventTopic::Progress]);
+            }
+            other => panic!("expected drop report, got {:?}", other),
+        }
+        assert_eq!(messages.len(), 5);
+        assert!(messages[1..].iter().all(|m| matches!(
+            m,
+            ServerMessage::Event {
+                event: GatewayEvent::ApprovalRequest { .. }
+            }
+        )));
+        assert!(queue.is_empty());
+        assert!(queue.drain().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lag_is_reported_and_wakes_writer() {
+        let queue = EventQueue::new(4);
+        queue.record_lag(7);
+        tokio::time::timeout(std::time::Duration::from_secs(1), queue.ready())
+            .await
+            .unwrap();
+        match queue.drain().as_slice() {
+            [ServerMessage::EventsDropped { count, topics }] => {
+                assert_eq!(*count, 7);
+                assert!(topics.is_empty());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -1,11 +1,18 @@
 //! Gateway event types and message protocol.
 
+use super::auth::AuthScope;
+use super::devices::{PairedDevice, PairingOffer};
+use super::forms::FormResolution;
+use super::pty::{PtyExitReason, PtySessionInfo, PtyStreamToken};
+use super::reload::ReloadReport;
+use super::tasks::{TaskOptions, TaskUpdate};
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Events emitted by the gateway to connected clients.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum GatewayEvent {
     /// A client has connected.
@@ -55,6 +62,9 @@ pub enum GatewayEvent {
         total_tool_calls: u64,
         total_llm_requests: u64,
         uptime_secs: u64,
+        /// Per-provider concurrency limiter statistics.
+        #[serde(default)]
+        llm_providers: Vec<crate::providers::LimiterStats>,
     },
     /// An approval request awaiting user decision.
     ApprovalRequest {
@@ -62,13 +72,167 @@ pub enum GatewayEvent {
         tool_name: String,
         description: String,
         risk_level: String,
+        /// When the approval times out, if a timeout policy applies.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires_at: Option<DateTime<Utc>>,
+    },
+    /// A pending approval timed out and was escalated to a channel; it can
+    /// still be decided until `expires_at`.
+    ApprovalEscalated {
+        approval_id: Uuid,
+        channel: String,
+        expires_at: DateTime<Utc>,
+    },
+    /// A pending approval expired without a decision and was denied.
+    ApprovalExpired {
+        approval_id: Uuid,
+        tool_name: String,
+        /// The `[safety.approval_timeouts]` entry that resolved it.
+        policy: String,
+        /// What happened, e.g. "denied, task aborted".
+        resolution: String,
     },
     /// A config snapshot was requested or changed.
     ConfigSnapshot { config_json: String },
+    /// A new session artifact was registered.
+    ArtifactCreated {
+        artifact: crate::artifacts::ArtifactRecord,
+    },
+    /// A canvas artifact is being pushed to the canvas again.
+    CanvasRepush {
+        artifact_id: Uuid,
+        message: crate::canvas::CanvasMessage,
+    },
+    /// Lifecycle update for a task submitted through the gateway.
+    TaskUpdate { task_id: Uuid, update: TaskUpdate },
+    /// The agent started an interactive command in a PTY session.
+    PtyOpened { session: PtySessionInfo },
+    /// Redacted output from a PTY session. Only delivered to connections
+    /// attached to that session.
+    PtyOutput { session_id: Uuid, data: String },
+    /// A PTY session's command ended.
+    PtyExited {
+        session_id: Uuid,
+        exit_code: Option<i32>,
+        reason: PtyExitReason,
+    },
+    /// The gateway re-read its configuration.
+    ConfigReloaded { report: ReloadReport },
+    /// A device completed QR pairing.
+    DevicePaired { device: PairedDevice },
+    /// A paired device was revoked; its connections are closed.
+    DeviceRevoked { device_id: Uuid },
+    /// A risky action from a voice turn was denied because it was not
+    /// confirmed by voice.
+    VoiceApprovalDenied {
+        tool_name: String,
+        description: String,
+        /// What was said instead, or `None` after silence.
+        heard: Option<String>,
+    },
+    /// The agent asks for input through a form. Answer with `SubmitForm` or
+    /// `POST /api/forms/{form_id}/submit`; `html` is the rendered form, which
+    /// posts to that endpoint. Sensitive fields carry no default value.
+    FormRequest {
+        form_id: Uuid,
+        spec: crate::canvas::FormSpec,
+        html: String,
+        /// Task waiting for the submission, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        task_id: Option<Uuid>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires_at: Option<DateTime<Utc>>,
+    },
+    /// A form was submitted or expired; clients still showing it should
+    /// close it.
+    FormResolved {
+        form_id: Uuid,
+        resolution: FormResolution,
+        /// The connection whose submission won, if it came over WebSocket.
+        #[serde(default)]
+        connection_id: Option<Uuid>,
+        /// Submitted values with sensitive fields redacted.
+        #[serde(default)]
+        values: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+/// Subscription topic of a [`GatewayEvent`]. Connections receive every
+/// topic until they send [`ClientMessage::Subscribe`] / `Unsubscribe`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EventTopic {
+    /// Approval requests awaiting a decision. Never dropped for slow clients.
+    Approvals,
+    /// Metrics and configuration snapshots.
+    Metrics,
+    /// Task lifecycle, tool execution, streamed output and errors.
+    Progress,
+    /// Artifacts, canvas pushes and forms.
+    Canvas,
+    /// Client connections, agents and nodes.
+    Sessions,
+    /// Messages received on channels.
+    Channels,
+    /// PTY sessions for interactive commands.
+    Terminal,
+}
+
+impl EventTopic {
+    /// Every topic, the default subscription.
+    pub const ALL: [EventTopic; 7] = [
+        EventTopic::Approvals,
+        EventTopic::Metrics,
+        EventTopic::Progress,
+        EventTopic::Canvas,
+        EventTopic::Sessions,
+        EventTopic::Channels,
+        EventTopic::Terminal,
+    ];
+}
+
+impl GatewayEvent {
+    /// The topic clients subscribe to for this event.
+    pub fn topic(&self) -> EventTopic {
+        match self {
+            GatewayEvent::ApprovalRequest { .. }
+            | GatewayEvent::ApprovalEscalated { .. }
+            | GatewayEvent::ApprovalExpired { .. }
+            | GatewayEvent::VoiceApprovalDenied { .. } => EventTopic::Approvals,
+            GatewayEvent::MetricsUpdate { .. }
+            | GatewayEvent::ConfigSnapshot { .. }
+            | GatewayEvent::ConfigReloaded { .. } => EventTopic::Metrics,
+            GatewayEvent::TaskSubmitted { .. }
+            | GatewayEvent::TaskProgress { .. }
+            | GatewayEvent::TaskCompleted { .. }
+            | GatewayEvent::TaskUpdate { .. }
+            | GatewayEvent::AssistantMessage { .. }
+            | GatewayEvent::StreamToken { .. }
+            | GatewayEvent::ToolExecution { .. }
+            | GatewayEvent::Error { .. } => EventTopic::Progress,
+            GatewayEvent::ArtifactCreated { .. }
+            | GatewayEvent::CanvasRepush { .. }
+            | GatewayEvent::FormRequest { .. }
+            | GatewayEvent::FormResolved { .. } => EventTopic::Canvas,
+            GatewayEvent::Connected { .. }
+            | GatewayEvent::Disconnected { .. }
+            | GatewayEvent::NodeTaskDispatched { .. }
+            | GatewayEvent::AgentSpawned { .. }
+            | GatewayEvent::AgentTerminated { .. }
+            | GatewayEvent::DevicePaired { .. }
+            | GatewayEvent::DeviceRevoked { .. } => EventTopic::Sessions,
+            GatewayEvent::ChannelMessageReceived { .. } => EventTopic::Channels,
+            GatewayEvent::PtyOpened { .. }
+            | GatewayEvent::PtyOutput { .. }
+            | GatewayEvent::PtyExited { .. } => EventTopic::Terminal,
+        }
+    }
 }
 
 /// Status of a tool execution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum ToolStatus {
     Started,
     Running,
@@ -77,15 +241,38 @@ pub enum ToolStatus {
 }
 
 /// Messages sent from clients to the gateway.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum ClientMessage {
-    /// Authenticate with a token.
-    Authenticate { token: String },
-    /// Submit a new task to the agent.
-    SubmitTask { description: String },
-    /// Cancel a running task.
-    CancelTask { task_id: Uuid },
+    /// Authenticate with a token. Clients should announce the protocol
+    /// version they were written against, and may name themselves so
+    /// operators can tell connections apart.
+    Authenticate {
+        token: String,
+        #[serde(default)]
+        protocol_version: Option<String>,
+        /// Human-readable name, e.g. "Laptop dashboard".
+        #[serde(default)]
+        client_name: Option<String>,
+        /// Kind of client, e.g. "dashboard" or "telegram-bridge".
+        #[serde(default)]
+        client_type: Option<String>,
+    },
+    /// Submit a new task to the agent. Requires the `tasks` auth scope.
+    SubmitTask {
+        #[serde(alias = "description")]
+        text: String,
+        /// Workspace to run in (defaults to the gateway host's workspace).
+        #[serde(default)]
+        workspace: Option<String>,
+        #[serde(default)]
+        options: TaskOptions,
+    },
+    /// Cancel a queued or running task. Requires the `tasks` auth scope.
+    CancelTask {
+        #[serde(alias = "id")]
+        task_id: Uuid,
+    },
     /// Request the current status.
     GetStatus,
     /// Keep-alive ping.
@@ -104,14 +291,65 @@ pub enum ClientMessage {
         approved: bool,
         reason: Option<String>,
     },
+    /// Start receiving events for `topics`, in addition to current ones.
+    Subscribe { topics: Vec<EventTopic> },
+    /// Stop receiving events for `topics`.
+    Unsubscribe { topics: Vec<EventTopic> },
+    /// Request a stream token for a PTY session, scoped to this connection.
+    PtyOpenStream { session_id: Uuid },
+    /// Attach to a PTY session with a stream token.
+    PtyAttach { token: String },
+    /// Type into an attached PTY session. Requires a writable attachment.
+    PtyInput { session_id: Uuid, data: String },
+    /// Resize an attached PTY session. Requires a writable attachment.
+    PtyResize {
+        session_id: Uuid,
+        cols: u16,
+        rows: u16,
+    },
+    /// Stop receiving a PTY session's output. The process keeps running.
+    PtyDetach { session_id: Uuid },
+    /// Kill an attached PTY session's process. Requires a writable attachment.
+    PtyKill { session_id: Uuid },
+    /// Create a QR pairing offer for a device limited to `scope`. Requires
+    /// the `tasks` scope on a non-device connection.
+    CreatePairing {
+        #[serde(default = "default_pairing_scope")]
+        scope: AuthScope,
+    },
+    /// List paired devices. Requires the `tasks` scope on a non-device connection.
+    ListDevices,
+    /// Revoke a paired device. Requires the `tasks` scope on a non-device connection.
+    RevokeDevice { device_id: Uuid },
+    /// Submit values for a form from a `FormRequest`, keyed by field name.
+    SubmitForm {
+        form_id: Uuid,
+        values: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+fn default_pairing_scope() -> AuthScope {
+    AuthScope::Status
 }
 
 /// Messages sent from the gateway to clients.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum ServerMessage {
     /// Authentication succeeded.
-    Authenticated { connection_id: Uuid },
+    Authenticated {
+        connection_id: Uuid,
+        /// The gateway's protocol version.
+        #[serde(default)]
+        protocol_version: String,
+    },
+    /// The client announced a protocol with a different major version. Sent
+    /// after `Authenticated`; the connection still receives every event.
+    ProtocolMismatch {
+        client_version: String,
+        server_version: String,
+        message: String,
+    },
     /// Authentication failed.
     AuthFailed { reason: String },
     /// A gateway event.
@@ -121,6 +359,8 @@ pub enum ServerMessage {
         connected_clients: usize,
         active_tasks: usize,
         uptime_secs: u64,
+        #[serde(default)]
+        protocol_version: String,
     },
     /// Pong response to a Ping.
     Pong { timestamp: DateTime<Utc> },
@@ -135,11 +375,51 @@ pub enum ServerMessage {
         total_tool_calls: u64,
         total_llm_requests: u64,
         uptime_secs: u64,
+        /// Per-provider concurrency limiter statistics.
+        #[serde(default)]
+        llm_providers: Vec<crate::providers::LimiterStats>,
     },
     /// Configuration snapshot.
     ConfigResponse { config_json: String },
     /// Approval decision acknowledgment.
     ApprovalAck { approval_id: Uuid, accepted: bool },
+    /// Task cancellation acknowledgment (`accepted` is false for unknown tasks).
+    CancelAck { task_id: Uuid, accepted: bool },
+    /// The connection's topics after a `Subscribe` or `Unsubscribe`.
+    Subscriptions { topics: Vec<EventTopic> },
+    /// Events were dropped because this client read too slowly. Sent before
+    /// the next delivered event; `topics` lists what was lost.
+    EventsDropped { count: u64, topics: Vec<EventTopic> },
+    /// A stream token for attaching to a PTY session.
+    PtyStream { stream: PtyStreamToken },
+    /// Attached to a PTY session; `scrollback` is its recent output.
+    PtyAttached {
+        session_id: Uuid,
+        scrollback: String,
+        writable: bool,
+    },
+    /// Acknowledgment of PTY input, resize, detach or kill.
+    PtyAck { session_id: Uuid, accepted: bool },
+    /// The gateway is shutting down. It stops accepting tasks, lets running
+    /// ones finish for up to `grace_secs`, then closes the connection.
+    ShuttingDown { grace_secs: u64 },
+    /// A pairing offer; `qr_png` is the base64-encoded QR code of its URL.
+    PairingOffer { offer: PairingOffer, qr_png: String },
+    /// Paired devices listing.
+    Devices { devices: Vec<PairedDevice> },
+    /// Result of a `SubmitForm`. A rejected submission carries `error`, and
+    /// `errors` for individual fields to show inline.
+    FormAck {
+        form_id: Uuid,
+        accepted: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        errors: Vec<crate::canvas::FieldError>,
+        /// Task started from the form's `on_submit` template.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        task_id: Option<Uuid>,
+    },
 }
 
 #[cfg(test)]
@@ -167,11 +447,36 @@ mod tests {
     fn test_client_message_serialization() {
         let msg = ClientMessage::Authenticate {
             token: "secret".into(),
+            protocol_version: Some("1.0".into()),
+            client_name: Some("Laptop dashboard".into()),
+            client_type: Some("dashboard".into()),
         };
         let json = serde_json::to_string(&msg).unwrap();
         let restored: ClientMessage = serde_json::from_str(&json).unwrap();
         match restored {
-            ClientMessage::Authenticate { token } => assert_eq!(token, "secret"),
+            ClientMessage::Authenticate {
+                token,
+                protocol_version,
+                client_name,
+                client_type,
+            } => {
+                assert_eq!(token, "secret");
+                assert_eq!(protocol_version.as_deref(), Some("1.0"));
+                assert_eq!(client_name.as_deref(), Some("Laptop dashboard"));
+                assert_eq!(client_type.as_deref(), Some("dashboard"));
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_authenticate_without_protocol_version() {
+        let restored: ClientMessage =
+            serde_json::from_str(r#"{"type":"Authenticate","token":"secret"}"#).unwrap();
+        match restored {
+            ClientMessage::Authenticate {
+                protocol_version, ..
+            } => assert!(protocol_version.is_none()),
             _ => panic!("Wrong variant"),
         }
     }
@@ -182,6 +487,7 @@ mod tests {
             connected_clients: 3,
             active_tasks: 1,
             uptime_secs: 3600,
+            protocol_version: "1.0".into(),
         };
         let json = serde_json::to_string(&msg).unwrap();
         let restored: ServerMessage = serde_json::from_str(&json).unwrap();
@@ -190,10 +496,12 @@ mod tests {
                 connected_clients,
                 active_tasks,
                 uptime_secs,
+                protocol_version,
             } => {
                 assert_eq!(connected_clients, 3);
                 assert_eq!(active_tasks, 1);
                 assert_eq!(uptime_secs, 3600);
+                assert_eq!(protocol_version, "1.0");
             }
             _ => panic!("Wrong variant"),
         }
@@ -257,23 +565,203 @@ mod tests {
                 total_tool_calls: 100,
                 total_llm_requests: 50,
                 uptime_secs: 3600,
+                llm_providers: vec![crate::providers::LimiterStats {
+                    key: "anthropic|default|ANTHROPIC_API_KEY".into(),
+                    max_concurrent: 4,
+                    in_flight: 2,
+                    ..Default::default()
+                }],
             },
             GatewayEvent::ApprovalRequest {
                 approval_id: Uuid::new_v4(),
                 tool_name: "shell_exec".into(),
                 description: "Run rm -rf".into(),
                 risk_level: "high".into(),
+                expires_at: Some(Utc::now()),
+            },
+            GatewayEvent::ApprovalEscalated {
+                approval_id: Uuid::new_v4(),
+                channel: "telegram".into(),
+                expires_at: Utc::now(),
+            },
+            GatewayEvent::ApprovalExpired {
+                approval_id: Uuid::new_v4(),
+                tool_name: "shell_exec".into(),
+                policy: "risk_levels.destructive".into(),
+                resolution: "denied, task aborted".into(),
             },
             GatewayEvent::ConfigSnapshot {
                 config_json: "{}".into(),
             },
+            GatewayEvent::ArtifactCreated {
+                artifact: crate::artifacts::ArtifactRecord::from_artifact(
+                    "file_write",
+                    &crate::types::Artifact::FileCreated {
+                        path: "notes.md".into(),
+                    },
+                    None,
+                    None,
+                )
+                .unwrap(),
+            },
+            GatewayEvent::CanvasRepush {
+                artifact_id: Uuid::new_v4(),
+                message: crate::canvas::CanvasMessage::Clear {
+                    target: crate::canvas::CanvasTarget::Broadcast,
+                },
+            },
+            GatewayEvent::TaskUpdate {
+                task_id: Uuid::new_v4(),
+                update: TaskUpdate::ToolStarted {
+                    tool_name: "file_read".into(),
+                    summary: None,
+                },
+            },
+            GatewayEvent::PtyOpened {
+                session: PtySessionInfo {
+                    id: Uuid::new_v4(),
+                    command: "git rebase -i HEAD~3".into(),
+                    cwd: "/tmp".into(),
+                    reason: Some("opens an editor".into()),
+                    started_at: Utc::now(),
+                    cols: 80,
+                    rows: 24,
+                    exit: None,
+                },
+            },
+            GatewayEvent::PtyOutput {
+                session_id: Uuid::new_v4(),
+                data: "pick abc123\r\n".into(),
+            },
+            GatewayEvent::PtyExited {
+                session_id: Uuid::new_v4(),
+                exit_code: Some(0),
+                reason: PtyExitReason::Exited,
+            },
+            GatewayEvent::ConfigReloaded {
+                report: ReloadReport {
+                    applied: vec!["gateway.auth_tokens".into()],
+                    deferred: vec!["gateway.port".into()],
+                    ..Default::default()
+                },
+            },
+            GatewayEvent::DevicePaired {
+                device: PairedDevice {
+                    identity: crate::pairing::DeviceIdentity {
+                        device_id: Uuid::new_v4(),
+                        device_name: "Pixel".into(),
+                        public_key: String::new(),
+                        created_at: Utc::now(),
+                        last_seen: Utc::now(),
+                    },
+                    platform: "android".into(),
+                    scope: AuthScope::Status,
+                },
+            },
+            GatewayEvent::DeviceRevoked {
+                device_id: Uuid::new_v4(),
+            },
+            GatewayEvent::VoiceApprovalDenied {
+                tool_name: "shell_exec".into(),
+                description: "Run make deploy".into(),
+                heard: Some("wait".into()),
+            },
+            GatewayEvent::FormRequest {
+                form_id: Uuid::new_v4(),
+                spec: serde_json::from_value(serde_json::json!({
+                    "fields": [{"name": "env", "label": "Env", "field_type": "text"}]
+                }))
+                .unwrap(),
+                html: "<form></form>".into(),
+                task_id: Some(Uuid::new_v4()),
+                expires_at: Some(Utc::now()),
+            },
+            GatewayEvent::FormResolved {
+                form_id: Uuid::new_v4(),
+                resolution: FormResolution::Submitted,
+                connection_id: None,
+                values: serde_json::json!({"env": "staging"})
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            },
         ];
 
         for event in &events {
             let json = serde_json::to_string(event).unwrap();
             let _: GatewayEvent = serde_json::from_str(&json).unwrap();
         }
-        assert_eq!(events.len(), 16);
+        assert_eq!(events.len(), 30);
+    }
+
+    #[test]
+    fn test_submit_task_accepts_legacy_fields() {
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type":"SubmitTask","description":"fix the build"}"#).unwrap();
+        match msg {
+            ClientMessage::SubmitTask {
+                text,
+                workspace,
+                options,
+            } => {
+                assert_eq!(text, "fix the build");
+                assert!(workspace.is_none());
+                assert!(options.max_iterations.is_none());
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        let id = Uuid::new_v4();
+        let cancel: ClientMessage =
+            serde_json::from_str(&format!(r#"{{"type":"CancelTask","id":"{}"}}"#, id)).unwrap();
+        assert!(matches!(cancel, ClientMessage::CancelTask { task_id } if task_id == id));
+    }
+
+    #[test]
+    fn test_event_topics_and_subscribe_messages() {
+        let approval = GatewayEvent::ApprovalRequest {
+            approval_id: Uuid::new_v4(),
+            tool_name: "shell_exec".into(),
+            description: "ls".into(),
+            risk_level: "low".into(),
+            expires_at: None,
+        };
+        assert_eq!(approval.topic(), EventTopic::Approvals);
+        assert_eq!(
+            GatewayEvent::StreamToken { token: "t".into() }.topic(),
+            EventTopic::Progress
+        );
+        assert_eq!(
+            GatewayEvent::AgentSpawned {
+                agent_id: "a".into(),
+                name: "n".into()
+            }
+            .topic(),
+            EventTopic::Sessions
+        );
+
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type":"Subscribe","topics":["approvals","metrics"]}"#)
+                .unwrap();
+        match msg {
+            ClientMessage::Subscribe { topics } => {
+                assert_eq!(topics, vec![EventTopic::Approvals, EventTopic::Metrics])
+            }
+            _ => panic!("Wrong variant"),
+        }
+        assert!(
+            serde_json::from_str::<ClientMessage>(r#"{"type":"Unsubscribe","topics":["bogus"]}"#)
+                .is_err()
+        );
+
+        let dropped = ServerMessage::EventsDropped {
+            count: 3,
+            topics: vec![EventTopic::Progress],
+        };
+        assert_eq!(
+            serde_json::to_string(&dropped).unwrap(),
+            r#"{"type":"EventsDropped","count":3,"topics":["progress"]}"#
+        );
     }
 
     #[test]
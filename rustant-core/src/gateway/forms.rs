@@ -0,0 +1,345 @@
+//! Canvas forms awaiting a submission.
+//!
+//! The gateway shows a registered form on every dashboard as a
+//! `GatewayEvent::FormRequest`. Clients answer with `ClientMessage::SubmitForm`
+//! or `POST /api/forms/{id}/submit`. Values are validated against the form's
+//! fields and the first valid submission wins: it resumes the task waiting in
+//! [`TaskProgress::wait_for_form`](super::TaskProgress::wait_for_form), or
+//! starts the form's `on_submit` task. Later submitters are told the form was
+//! already submitted, and every client gets a `FormResolved` event. Forms
+//! with `expires_in_secs` stop accepting submissions when it passes.
+
+use crate::canvas::{FieldError, FormSpec, redact_submission, validate_submission};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+/// Resolved forms remembered so late submitters learn what happened.
+const MAX_CLOSED_FORMS: usize = 256;
+
+/// A form shown to clients, accepting submissions.
+#[derive(Debug, Clone)]
+pub struct PendingForm {
+    pub id: Uuid,
+    pub spec: FormSpec,
+    /// Task blocked on the form, if any.
+    pub task_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// When submissions stop being accepted, from `spec.expires_in_secs`.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl PendingForm {
+    pub fn new(spec: FormSpec, task_id: Option<Uuid>) -> Self {
+        let created_at = Utc::now();
+        let expires_at = spec.expires_in_secs.and_then(|secs| {
+            let window = chrono::Duration::from_std(std::time::Duration::from_secs(secs)).ok()?;
+            created_at.checked_add_signed(window)
+        });
+        Self {
+            id: Uuid::new_v4(),
+            spec,
+            task_id,
+            created_at,
+            expires_at,
+        }
+    }
+
+    /// Whether the form's submission window has passed at `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| now >= at)
+    }
+}
+
+/// What a task waiting on a form receives.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormOutcome {
+    /// The first valid submission, normalized to the field types. Sensitive
+    /// fields carry their real values; only the waiting task sees them.
+    Submitted {
+        values: Map<String, Value>,
+        connection_id: Option<Uuid>,
+    },
+    /// Nobody submitted the form before it expired.
+    Expired,
+}
+
+/// How a form was closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FormResolution {
+    Submitted,
+    Expired,
+}
+
+/// A form that no longer accepts submissions.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClosedForm {
+    pub id: Uuid,
+    pub title: Option<String>,
+    pub task_id: Option<Uuid>,
+    pub resolution: FormResolution,
+    /// The connection that submitted it; `None` for REST or expiry.
+    pub connection_id: Option<Uuid>,
+    /// Submitted values with sensitive fields redacted.
+    pub values: Map<String, Value>,
+    pub closed_at: DateTime<Utc>,
+}
+
+/// The accepted submission, as reported back to the submitter.
+#[derive(Debug, Clone)]
+pub struct FormReceipt {
+    /// Submitted values with sensitive fields redacted.
+    pub values: Map<String, Value>,
+    /// Task started from the form's `on_submit` template.
+    pub task_id: Option<Uuid>,
+}
+
+/// Why a submission was rejected.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FormSubmitError {
+    #[error("Form not found")]
+    NotFound,
+    #[error("Form was already submitted by another client")]
+    AlreadySubmitted,
+    #[error("Form has expired and no longer accepts submissions")]
+    Expired,
+    #[error("Invalid submission: {}", join_errors(.0))]
+    Invalid(Vec<FieldError>),
+}
+
+fn join_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// A submission the registry accepted.
+#[derive(Debug)]
+pub(crate) struct AcceptedSubmission {
+    pub form: PendingForm,
+    /// Normalized values, sensitive fields included.
+    pub values: Map<String, Value>,
+    /// Whether a waiting task received the values.
+    pub delivered: bool,
+}
+
+/// Pending forms, the tasks waiting on them, and recently closed forms.
+#[derive(Debug, Default)]
+pub(crate) struct FormRegistry {
+    pending: HashMap<Uuid, PendingForm>,
+    waiters: HashMap<Uuid, oneshot::Sender<FormOutcome>>,
+    closed: VecDeque<ClosedForm>,
+}
+
+impl FormRegistry {
+    /// Accept submissions for `form`, delivering the first to `waiter`.
+    pub fn add(&mut self, form: PendingForm, waiter: Option<oneshot::Sender<FormOutcome>>) {
+        if let Some(waiter) = waiter {
+            self.waiters.insert(form.id, waiter);
+        }
+        self.pending.insert(form.id, form);
+    }
+
+    /// Validate and accept a submission. The form closes on success; an
+    /// invalid submission leaves it open for a corrected one.
+    pub fn submit(
+        &mut self,
+        form_id: &Uuid,
+        values: &Map<String, Value>,
+        connection_id: Option<Uuid>,
+    ) -> Result<AcceptedSubmission, FormSubmitError> {
+        let Some(form) = self.pending.get(form_id) else {
+            return Err(match self.closed.iter().find(|c| c.id == *form_id) {
+                Some(closed) if closed.resolution == FormResolution::Expired => {
+                    FormSubmitError::Expired
+                }
+                Some(_) => FormSubmitError::AlreadySubmitted,
+                None => FormSubmitError::NotFound,
+            });
+        };
+        if form.is_expired(Utc::now()) {
+            return Err(FormSubmitError::Expired);
+        }
+        let values = validate_submission(&form.spec, values).map_err(FormSubmitError::Invalid)?;
+
+        let form = self.pending.remove(form_id).expect("form is pending");
+        let delivered = self.waiters.remove(form_id).is_some_and(|waiter| {
+            waiter
+                .send(FormOutcome::Submitted {
+                    values: values.clone(),
+                    connection_id,
+                })
+                .is_ok()
+        });
+        self.close(ClosedForm {
+            id: form.id,
+            title: form.spec.title.clone(),
+            task_id: form.task_id,
+            resolution: FormResolution::Submitted,
+            connection_id,
+            values: redact_submission(&form.spec, &values),
+            closed_at: Utc::now(),
+        });
+        Ok(AcceptedSubmission {
+            form,
+            values,
+            delivered,
+        })
+    }
+
+    /// Close a pending form as expired and tell its waiter. Returns `None`
+    /// if it was submitted or closed meanwhile.
+    pub fn expire(&mut self, form_id: &Uuid) -> Option<ClosedForm> {
+        let form = self.pending.remove(form_id)?;
+        if let Some(waiter) = self.waiters.remove(form_id) {
+            let _ = waiter.send(FormOutcome::Expired);
+        }
+        let closed = ClosedForm {
+            id: form.id,
+            title: form.spec.title,
+            task_id: form.task_id,
+            resolution: FormResolution::Expired,
+            connection_id: None,
+            values: Map::new(),
+            closed_at: Utc::now(),
+        };
+        self.close(closed.clone());
+        Some(closed)
+    }
+
+    /// Expire every pending form whose window has passed at `now`.
+    pub fn expire_due(&mut self, now: DateTime<Utc>) -> Vec<ClosedForm> {
+        let due: Vec<Uuid> = self
+            .pending
+            .values()
+            .filter(|f| f.is_expired(now))
+            .map(|f| f.id)
+            .collect();
+        due.iter().filter_map(|id| self.expire(id)).collect()
+    }
+
+    /// Pending forms, oldest first.
+    pub fn pending(&self) -> Vec<&PendingForm> {
+        let mut forms: Vec<_> = self.pending.values().collect();
+        forms.sort_by_key(|f| f.created_at);
+        forms
+    }
+
+    /// Recently closed forms, oldest first.
+    pub fn closed(&self) -> &VecDeque<ClosedForm> {
+        &self.closed
+    }
+
+    fn close(&mut self, closed: ClosedForm) {
+        if self.closed.len() == MAX_CLOSED_FORMS {
+            self.closed.pop_front();
+        }
+        self.closed.push_back(closed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn form(expires_in_secs: Option<u64>) -> PendingForm {
+        let mut spec: FormSpec = serde_json::from_value(json!({
+            "title": "Credentials",
+            "fields": [
+                {"name": "user", "label": "User", "field_type": "text", "required": true},
+                {"name": "password", "label": "Password", "field_type": "text",
+                 "required": true, "sensitive": true}
+            ]
+        }))
+        .unwrap();
+        spec.expires_in_secs = expires_in_secs;
+        PendingForm::new(spec, Some(Uuid::new_v4()))
+    }
+
+    fn values(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_first_valid_submission_wins() {
+        let mut registry = FormRegistry::default();
+        let form = form(None);
+        let id = form.id;
+        let (tx, mut rx) = oneshot::channel();
+        registry.add(form, Some(tx));
+
+        let invalid = registry.submit(&id, &values(json!({"user": "ada"})), None);
+        match invalid {
+            Err(FormSubmitError::Invalid(errors)) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].field, "password");
+            }
+            other => panic!("expected invalid, got {:?}", other),
+        }
+        assert_eq!(registry.pending().len(), 1);
+
+        let first = Uuid::new_v4();
+        let accepted = registry
+            .submit(
+                &id,
+                &values(json!({"user": "ada", "password": "s3cret"})),
+                Some(first),
+            )
+            .unwrap();
+        assert!(accepted.delivered);
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            FormOutcome::Submitted {
+                values: values(json!({"user": "ada", "password": "s3cret"})),
+                connection_id: Some(first),
+            }
+        );
+
+        let second = registry.submit(
+            &id,
+            &values(json!({"user": "bob", "password": "x"})),
+            Some(Uuid::new_v4()),
+        );
+        assert_eq!(second.unwrap_err(), FormSubmitError::AlreadySubmitted);
+        let closed = &registry.closed()[0];
+        assert_eq!(closed.values["password"], crate::canvas::forms::REDACTED);
+        assert_eq!(closed.connection_id, Some(first));
+    }
+
+    #[test]
+    fn test_expired_form_rejects_submissions() {
+        let mut registry = FormRegistry::default();
+        let form = form(Some(0));
+        let id = form.id;
+        let (tx, mut rx) = oneshot::channel();
+        registry.add(form, Some(tx));
+
+        let submitted = values(json!({"user": "ada", "password": "s3cret"}));
+        assert_eq!(
+            registry.submit(&id, &submitted, None).unwrap_err(),
+            FormSubmitError::Expired
+        );
+        let expired = registry.expire_due(Utc::now());
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].resolution, FormResolution::Expired);
+        assert_eq!(rx.try_recv().unwrap(), FormOutcome::Expired);
+        assert_eq!(
+            registry.submit(&id, &submitted, None).unwrap_err(),
+            FormSubmitError::Expired
+        );
+        assert_eq!(
+            registry
+                .submit(&Uuid::new_v4(), &submitted, None)
+                .unwrap_err(),
+            FormSubmitError::NotFound
+        );
+    }
+}
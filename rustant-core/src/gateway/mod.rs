@@ -4,24 +4,51 @@
 //! external clients and the Rustant agent. Supports authentication,
 //! connection management, session lifecycle, and a structured event protocol.
 
+pub mod approvals;
 mod auth;
 pub mod channel_bridge;
 mod connection;
+pub mod devices;
+mod event_queue;
 mod events;
+pub mod forms;
 pub mod node_bridge;
+mod protocol;
+pub mod pty;
+mod reload;
 mod server;
 mod session;
+pub mod tasks;
 
-pub use auth::GatewayAuth;
+pub use approvals::{ApprovalEscalator, ChannelEscalator};
+pub use auth::{AuthScope, GatewayAuth};
 pub use channel_bridge::ChannelBridge;
-pub use connection::ConnectionManager;
-pub use events::{ClientMessage, GatewayEvent, ServerMessage};
+pub use connection::{ConnectionInfo, ConnectionManager, DisconnectCounts, DisconnectReason};
+pub use devices::{
+    DeviceGrant, DevicePairingRequest, DeviceRegistry, PairedDevice, PairingError, PairingOffer,
+    qr_png, qr_terminal,
+};
+pub use event_queue::EventQueue;
+pub use events::{ClientMessage, EventTopic, GatewayEvent, ServerMessage};
+pub use forms::{
+    ClosedForm, FormOutcome, FormReceipt, FormResolution, FormSubmitError, PendingForm,
+};
 pub use node_bridge::NodeBridge;
+pub use protocol::{
+    PROTOCOL_HEADER, PROTOCOL_VERSION, breaking_changes, protocol_major, protocol_schema,
+    protocol_typescript, version_mismatch,
+};
+pub use pty::{PtyConfig, PtyError, PtyManager, PtySessionInfo, PtySpawn, PtyStreamToken};
+pub use reload::{ConfigLoader, ReloadHandler, ReloadReport};
 pub use server::{
-    GatewayServer, PendingApproval, SharedGateway, StatusProvider, router as gateway_router,
-    run as run_gateway,
+    GatewayServer, PendingApproval, SharedGateway, ShutdownPhase, StatusProvider,
+    router as gateway_router, run as run_gateway, shutdown as shutdown_gateway,
 };
 pub use session::{GatewaySession, SessionManager, SessionState};
+pub use tasks::{
+    TaskOptions, TaskProgress, TaskProgressCallback, TaskRequest, TaskRunner, TaskSummary,
+    TaskUpdate, dispatch_tasks,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -34,8 +61,11 @@ pub struct GatewayConfig {
     pub host: String,
     /// Port to listen on.
     pub port: u16,
-    /// Valid authentication tokens.
+    /// Valid authentication tokens (read-only scope).
     pub auth_tokens: Vec<String>,
+    /// Tokens that may also submit and cancel tasks.
+    #[serde(default)]
+    pub task_tokens: Vec<String>,
     /// Maximum concurrent WebSocket connections.
     pub max_connections: usize,
     /// Session timeout in seconds (0 = no timeout).
@@ -43,12 +73,74 @@ pub struct GatewayConfig {
     /// Broadcast channel capacity for event distribution to WebSocket connections.
     #[serde(default = "default_broadcast_capacity")]
     pub broadcast_capacity: usize,
+    /// Events buffered per connection for a slow client before the oldest
+    /// progress events are dropped. Approval requests are never dropped.
+    #[serde(default = "default_event_queue_capacity")]
+    pub event_queue_capacity: usize,
+    /// Maximum number of submitted tasks running at once.
+    #[serde(default = "default_max_concurrent_tasks")]
+    pub max_concurrent_tasks: usize,
+    /// Maximum number of submitted tasks waiting for a free slot.
+    #[serde(default = "default_max_queued_tasks")]
+    pub max_queued_tasks: usize,
+    /// Limits for PTY sessions running interactive commands.
+    #[serde(default)]
+    pub pty: PtyConfig,
+    /// QR device pairing.
+    #[serde(default)]
+    pub pairing: PairingConfig,
+    /// Seconds between keep-alive pings to each client. A client silent for
+    /// two intervals, not even answering pings, is disconnected as stale
+    /// (0 = never ping).
+    #[serde(default = "default_ping_interval_secs")]
+    pub ping_interval_secs: u64,
+}
+
+/// Settings for pairing phones and other devices by QR code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingConfig {
+    /// Base URL devices use to reach the gateway, e.g. a LAN address or
+    /// tunnel. Defaults to `http://<host>:<port>`.
+    #[serde(default)]
+    pub public_url: Option<String>,
+    /// Seconds a pairing QR code stays valid.
+    #[serde(default = "default_challenge_ttl_secs")]
+    pub challenge_ttl_secs: u64,
+}
+
+fn default_challenge_ttl_secs() -> u64 {
+    devices::DEFAULT_CHALLENGE_TTL_SECS
+}
+
+impl Default for PairingConfig {
+    fn default() -> Self {
+        Self {
+            public_url: None,
+            challenge_ttl_secs: default_challenge_ttl_secs(),
+        }
+    }
 }
 
 fn default_broadcast_capacity() -> usize {
     256
 }
 
+fn default_event_queue_capacity() -> usize {
+    256
+}
+
+fn default_ping_interval_secs() -> u64 {
+    30
+}
+
+fn default_max_concurrent_tasks() -> usize {
+    1
+}
+
+fn default_max_queued_tasks() -> usize {
+    16
+}
+
 impl Default for GatewayConfig {
     fn default() -> Self {
         Self {
@@ -56,9 +148,16 @@ impl Default for GatewayConfig {
             host: "127.0.0.1".to_string(),
             port: 8080,
             auth_tokens: Vec::new(),
+            task_tokens: Vec::new(),
             max_connections: 10,
             session_timeout_secs: 3600,
             broadcast_capacity: 256,
+            event_queue_capacity: default_event_queue_capacity(),
+            max_concurrent_tasks: default_max_concurrent_tasks(),
+            max_queued_tasks: default_max_queued_tasks(),
+            pty: PtyConfig::default(),
+            pairing: PairingConfig::default(),
+            ping_interval_secs: default_ping_interval_secs(),
         }
     }
 }
@@ -85,14 +184,39 @@ mod tests {
             host: "0.0.0.0".into(),
             port: 9090,
             auth_tokens: vec!["token1".into()],
+            task_tokens: vec!["token2".into()],
             max_connections: 50,
             session_timeout_secs: 7200,
             broadcast_capacity: 256,
+            event_queue_capacity: 64,
+            max_concurrent_tasks: 2,
+            max_queued_tasks: 8,
+            pty: PtyConfig {
+                max_sessions: 2,
+                ..Default::default()
+            },
+            pairing: PairingConfig::default(),
+            ping_interval_secs: 15,
         };
         let json = serde_json::to_string(&config).unwrap();
         let restored: GatewayConfig = serde_json::from_str(&json).unwrap();
         assert!(restored.enabled);
         assert_eq!(restored.port, 9090);
         assert_eq!(restored.auth_tokens.len(), 1);
+        assert_eq!(restored.task_tokens.len(), 1);
+        assert_eq!(restored.max_concurrent_tasks, 2);
+        assert_eq!(restored.pty.max_sessions, 2);
+    }
+
+    #[test]
+    fn test_gateway_config_task_defaults_when_missing() {
+        let json = r#"{"enabled":true,"host":"127.0.0.1","port":8080,"auth_tokens":[],"max_connections":10,"session_timeout_secs":0}"#;
+        let config: GatewayConfig = serde_json::from_str(json).unwrap();
+        assert!(config.task_tokens.is_empty());
+        assert_eq!(config.max_concurrent_tasks, 1);
+        assert_eq!(config.max_queued_tasks, 16);
+        assert_eq!(config.event_queue_capacity, 256);
+        assert_eq!(config.pty.max_sessions, 4);
+        assert_eq!(config.ping_interval_secs, 30);
     }
 }
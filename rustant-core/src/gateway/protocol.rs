@@ -0,0 +1,594 @@
+//! Gateway protocol versioning and schema.
+//!
+//! [`PROTOCOL_VERSION`] is `major.minor`. A minor bump may only add optional
+//! fields, messages or enum values, which older clients ignore. Removing,
+//! renaming or retyping anything, or adding a required field, needs a major
+//! bump. [`breaking_changes`] checks two [`protocol_schema`] snapshots against
+//! those rules, and [`protocol_typescript`] renders the schema as TypeScript
+//! declarations for the dashboard.
+
+use super::events::{ClientMessage, ServerMessage};
+use schemars::r#gen::SchemaSettings;
+use serde_json::{Map, Value, json};
+use std::collections::HashSet;
+
+/// Version of the WebSocket and REST protocol, `major.minor`.
+pub const PROTOCOL_VERSION: &str = "1.0";
+
+/// Response header carrying [`PROTOCOL_VERSION`] on every REST response.
+pub const PROTOCOL_HEADER: &str = "x-rustant-protocol";
+
+static MISSING: Value = Value::Null;
+
+/// The major component of a `major.minor` version.
+pub fn protocol_major(version: &str) -> Option<u32> {
+    version.trim().split('.').next()?.parse().ok()
+}
+
+/// The warning for a client that announced `client_version` when
+/// authenticating, if its major version differs from the gateway's.
+pub fn version_mismatch(client_version: &str) -> Option<ServerMessage> {
+    if protocol_major(client_version) == protocol_major(PROTOCOL_VERSION) {
+        return None;
+    }
+    Some(ServerMessage::ProtocolMismatch {
+        client_version: client_version.to_string(),
+        server_version: PROTOCOL_VERSION.to_string(),
+        message: format!(
+            "Client protocol {} differs from gateway protocol {}; all events are still sent, \
+             but some may not be understood",
+            client_version, PROTOCOL_VERSION
+        ),
+    })
+}
+
+/// JSON Schema of every client and server message, with the protocol
+/// version. The `client` and `server` properties reference the two message
+/// types; everything they use is under `definitions`.
+pub fn protocol_schema() -> Value {
+    let mut generator = SchemaSettings::draft07().into_generator();
+    let client = generator.subschema_for::<ClientMessage>();
+    let server = generator.subschema_for::<ServerMessage>();
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Rustant gateway protocol",
+        "protocol_version": PROTOCOL_VERSION,
+        "type": "object",
+        "properties": { "client": client, "server": server },
+        "definitions": generator.take_definitions(),
+    })
+}
+
+/// Changes from schema `old` to schema `new` that would break a client
+/// written against `old`, one line each. Added optional fields, messages and
+/// enum values are compatible; removed or renamed ones, changed types and new
+/// required fields are not.
+pub fn breaking_changes(old: &Value, new: &Value) -> Vec<String> {
+    let mut diff = SchemaDiff {
+        old_defs: definitions(old),
+        new_defs: definitions(new),
+        seen: HashSet::new(),
+        breaks: Vec::new(),
+    };
+    for (root, name) in [("client", "ClientMessage"), ("server", "ServerMessage")] {
+        let new_root = new.pointer(&format!("/properties/{}", root));
+        match (old.pointer(&format!("/properties/{}", root)), new_root) {
+            (Some(old_root), Some(new_root)) => diff.compare(name, old_root, new_root),
+            (Some(_), None) => diff.breaks.push(format!("{}: removed", name)),
+            _ => {}
+        }
+    }
+    diff.breaks
+}
+
+fn definitions(schema: &Value) -> &Map<String, Value> {
+    static EMPTY: std::sync::OnceLock<Map<String, Value>> = std::sync::OnceLock::new();
+    schema
+        .get("definitions")
+        .and_then(Value::as_object)
+        .unwrap_or_else(|| EMPTY.get_or_init(Map::new))
+}
+
+struct SchemaDiff<'a> {
+    old_defs: &'a Map<String, Value>,
+    new_defs: &'a Map<String, Value>,
+    /// Definition pairs already compared, so recursive types terminate.
+    seen: HashSet<(String, String)>,
+    breaks: Vec<String>,
+}
+
+impl<'a> SchemaDiff<'a> {
+    fn compare(&mut self, path: &str, old: &'a Value, new: &'a Value) {
+        let (old, old_ref) = resolve(self.old_defs, old);
+        let (new, new_ref) = resolve(self.new_defs, new);
+        if let (Some(o), Some(n)) = (old_ref, new_ref)
+            && !self.seen.insert((o.to_string(), n.to_string()))
+        {
+            return;
+        }
+
+        let (old_variants, new_variants) = (variants(old), variants(new));
+        if old_variants.is_some() || new_variants.is_some() {
+            let new_variants = new_variants.unwrap_or_else(|| vec![new]);
+            for old_variant in old_variants.unwrap_or_else(|| vec![old]) {
+                let key = variant_key(self.old_defs, old_variant);
+                match new_variants
+                    .iter()
+                    .find(|v| variant_key(self.new_defs, v) == key)
+                    .copied()
+                {
+                    Some(new_variant) => {
+                        self.compare(&format!("{}.{}", path, key), old_variant, new_variant)
+                    }
+                    None => self.breaks.push(format!(
+                        "{}: variant `{}` was removed or renamed",
+                        path, key
+                    )),
+                }
+            }
+            return;
+        }
+
+        if let (Some(old_values), Some(new_values)) = (
+            old.get("enum").and_then(Value::as_array),
+            new.get("enum").and_then(Value::as_array),
+        ) {
+            for value in old_values.iter().filter(|v| !new_values.contains(v)) {
+                self.breaks
+                    .push(format!("{}: value {} was removed or renamed", path, value));
+            }
+        }
+
+        let (mut old_types, mut new_types) = (types(old), types(new));
+        old_types.sort_unstable();
+        new_types.sort_unstable();
+        if !old_types.is_empty() && !new_types.is_empty() && old_types != new_types {
+            self.breaks.push(format!(
+                "{}: type changed from {} to {}",
+                path,
+                old_types.join(" | "),
+                new_types.join(" | ")
+            ));
+            return;
+        }
+
+        let old_props = old.get("properties").and_then(Value::as_object);
+        let new_props = new.get("properties").and_then(Value::as_object);
+        if let Some(old_props) = old_props {
+            for (name, old_prop) in old_props {
+                match new_props.and_then(|p| p.get(name)) {
+                    Some(new_prop) => {
+                        self.compare(&format!("{}.{}", path, name), old_prop, new_prop)
+                    }
+                    None => self
+                        .breaks
+                        .push(format!("{}: field `{}` was removed or renamed", path, name)),
+                }
+            }
+        }
+        let old_required = required(old);
+        for name in required(new) {
+            if old_required.contains(&name) {
+                continue;
+            }
+            if old_props.is_some_and(|p| p.contains_key(name)) {
+                self.breaks
+                    .push(format!("{}: field `{}` became required", path, name));
+            } else {
+                self.breaks
+                    .push(format!("{}: new required field `{}`", path, name));
+            }
+        }
+
+        match (old.get("items"), new.get("items")) {
+            (Some(Value::Array(old_items)), Some(Value::Array(new_items))) => {
+                if old_items.len() != new_items.len() {
+                    self.breaks.push(format!(
+                        "{}: tuple length changed from {} to {}",
+                        path,
+                        old_items.len(),
+                        new_items.len()
+                    ));
+                }
+                for (i, (o, n)) in old_items.iter().zip(new_items).enumerate() {
+                    self.compare(&format!("{}[{}]", path, i), o, n);
+                }
+            }
+            (Some(o), Some(n)) => self.compare(&format!("{}[]", path), o, n),
+            _ => {}
+        }
+        if let (Some(o @ Value::Object(_)), Some(n @ Value::Object(_))) = (
+            old.get("additionalProperties"),
+            new.get("additionalProperties"),
+        ) {
+            self.compare(&format!("{}{{}}", path), o, n);
+        }
+    }
+}
+
+/// Follow `$ref`s and single-element `allOf` wrappers, returning the schema
+/// and the last definition name passed through.
+fn resolve<'v>(
+    defs: &'v Map<String, Value>,
+    mut schema: &'v Value,
+) -> (&'v Value, Option<&'v str>) {
+    let mut name = None;
+    for _ in 0..16 {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let def = reference.rsplit('/').next().unwrap_or(reference);
+            name = Some(def);
+            schema = defs.get(def).unwrap_or(&MISSING);
+        } else if let Some([inner]) = schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+        {
+            schema = inner;
+        } else {
+            break;
+        }
+    }
+    (schema, name)
+}
+
+fn variants(schema: &Value) -> Option<Vec<&Value>> {
+    ["oneOf", "anyOf"]
+        .iter()
+        .find_map(|key| schema.get(*key).and_then(Value::as_array))
+        .map(|v| v.iter().collect())
+}
+
+/// A stable name for a union member: the tag value of an internally tagged
+/// variant, otherwise the referenced definition or the JSON type.
+fn variant_key(defs: &Map<String, Value>, variant: &Value) -> String {
+    let (resolved, name) = resolve(defs, variant);
+    if let Some(tag) = tag_value(resolved) {
+        return tag;
+    }
+    if let Some(name) = name {
+        return name.to_string();
+    }
+    if let Some(values) = resolved.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("|");
+    }
+    let types = types(resolved);
+    if types.is_empty() {
+        "any".to_string()
+    } else {
+        types.join("|")
+    }
+}
+
+/// The value of a required property that allows exactly one string, e.g.
+/// `"type": "Authenticate"`.
+fn tag_value(schema: &Value) -> Option<String> {
+    let props = schema.get("properties")?.as_object()?;
+    required(schema).into_iter().find_map(|name| {
+        match props.get(name)?.get("enum")?.as_array()?.as_slice() {
+            [Value::String(value)] => Some(value.clone()),
+            _ => None,
+        }
+    })
+}
+
+fn types(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn required(schema: &Value) -> Vec<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// TypeScript declarations (`.d.ts`) for a [`protocol_schema`]: one exported
+/// type per definition, plus the protocol version.
+pub fn protocol_typescript(schema: &Value) -> String {
+    let version = schema
+        .get("protocol_version")
+        .and_then(Value::as_str)
+        .unwrap_or(PROTOCOL_VERSION);
+    let mut out = String::from(
+        "// Generated by `rustant dev gen-client` from the gateway protocol schema. Do not edit.\n\n",
+    );
+    out.push_str(&format!(
+        "/** Send as `protocol_version` in `Authenticate`. */\nexport declare const PROTOCOL_VERSION: \"{}\";\n",
+        version
+    ));
+    for (name, def) in definitions(schema) {
+        out.push('\n');
+        if let Some(description) = def.get("description").and_then(Value::as_str) {
+            out.push_str(&doc_comment(description, ""));
+        }
+        out.push_str(&format!("export type {} = {};\n", name, ts_type(def, "")));
+    }
+    out
+}
+
+fn doc_comment(description: &str, indent: &str) -> String {
+    let lines: Vec<&str> = description.lines().collect();
+    if lines.len() == 1 {
+        return format!("{}/** {} */\n", indent, lines[0]);
+    }
+    let mut doc = format!("{}/**\n", indent);
+    for line in lines {
+        doc.push_str(&format!("{} * {}\n", indent, line).replace(" * \n", " *\n"));
+    }
+    doc.push_str(&format!("{} */\n", indent));
+    doc
+}
+
+fn ts_type(schema: &Value, indent: &str) -> String {
+    let map = match schema {
+        Value::Bool(true) => return "unknown".to_string(),
+        Value::Bool(false) => return "never".to_string(),
+        Value::Object(map) => map,
+        _ => return "unknown".to_string(),
+    };
+    if let Some(reference) = map.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+    if let Some(members) = ["oneOf", "anyOf"]
+        .iter()
+        .find_map(|key| map.get(*key).and_then(Value::as_array))
+    {
+        let rendered: Vec<String> = members.iter().map(|m| ts_type(m, indent)).collect();
+        if rendered.iter().any(|r| r.contains('\n')) {
+            let inner = format!("{}  ", indent);
+            return members
+                .iter()
+                .zip(&rendered)
+                .map(|(member, r)| {
+                    let doc = member
+                        .get("description")
+                        .and_then(Value::as_str)
+                        .map(|d| doc_comment(d, &inner))
+                        .unwrap_or_default();
+                    format!("\n{}{}| {}", doc, inner, r.replace('\n', "\n  "))
+                })
+                .collect();
+        }
+        return rendered.join(" | ");
+    }
+    if let Some([inner]) = map
+        .get("allOf")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        return ts_type(inner, indent);
+    }
+    if let Some(values) = map.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    if let Some(value) = map.get("const") {
+        return value.to_string();
+    }
+    let rendered: Vec<String> = types(schema)
+        .into_iter()
+        .map(|t| match t {
+            "string" => "string".to_string(),
+            "integer" | "number" => "number".to_string(),
+            "boolean" => "boolean".to_string(),
+            "null" => "null".to_string(),
+            "array" => array_type(map, indent),
+            "object" => object_type(map, indent),
+            _ => "unknown".to_string(),
+        })
+        .collect();
+    if rendered.is_empty() {
+        "unknown".to_string()
+    } else {
+        rendered.join(" | ")
+    }
+}
+
+fn array_type(map: &Map<String, Value>, indent: &str) -> String {
+    match map.get("items") {
+        Some(Value::Array(items)) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(|i| ts_type(i, indent))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Some(item) => {
+            let item = ts_type(item, indent);
+            if item.contains(' ') {
+                format!("({})[]", item)
+            } else {
+                format!("{}[]", item)
+            }
+        }
+        None => "unknown[]".to_string(),
+    }
+}
+
+fn object_type(map: &Map<String, Value>, indent: &str) -> String {
+    let Some(props) = map.get("properties").and_then(Value::as_object) else {
+        return match map.get("additionalProperties") {
+            Some(value @ Value::Object(_)) => format!("Record<string, {}>", ts_type(value, indent)),
+            _ => "Record<string, unknown>".to_string(),
+        };
+    };
+    let required: Vec<&str> = map
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let inner = format!("{}  ", indent);
+    let mut out = String::from("{\n");
+    for (name, prop) in props {
+        if let Some(description) = prop.get("description").and_then(Value::as_str) {
+            out.push_str(&doc_comment(description, &inner));
+        }
+        let optional = if required.contains(&name.as_str()) {
+            ""
+        } else {
+            "?"
+        };
+        let key = if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            name.clone()
+        } else {
+            format!("{:?}", name)
+        };
+        out.push_str(&format!(
+            "{}{}{}: {};\n",
+            inner,
+            key,
+            optional,
+            ts_type(prop, &inner)
+        ));
+    }
+    out.push_str(indent);
+    out.push('}');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tagged(schema: Value) -> Value {
+        json!({
+            "properties": { "client": { "$ref": "#/definitions/ClientMessage" } },
+            "definitions": { "ClientMessage": schema },
+        })
+    }
+
+    fn variant(tag: &str, props: Value, required: &[&str]) -> Value {
+        let mut properties = props.as_object().cloned().unwrap_or_default();
+        properties.insert("type".into(), json!({ "type": "string", "enum": [tag] }));
+        let mut req: Vec<&str> = vec!["type"];
+        req.extend_from_slice(required);
+        json!({ "type": "object", "required": req, "properties": properties })
+    }
+
+    #[test]
+    fn test_version_mismatch_only_on_major() {
+        assert!(version_mismatch(PROTOCOL_VERSION).is_none());
+        assert!(version_mismatch("1.7").is_none());
+        match version_mismatch("2.0") {
+            Some(ServerMessage::ProtocolMismatch {
+                client_version,
+                server_version,
+                ..
+            }) => {
+                assert_eq!(client_version, "2.0");
+                assert_eq!(server_version, PROTOCOL_VERSION);
+            }
+            other => panic!("expected ProtocolMismatch, got {:?}", other),
+        }
+        assert!(version_mismatch("garbage").is_some());
+    }
+
+    #[test]
+    fn test_schema_covers_messages_and_is_self_compatible() {
+        let schema = protocol_schema();
+        assert_eq!(schema["protocol_version"], PROTOCOL_VERSION);
+        let defs = schema["definitions"].as_object().unwrap();
+        for name in [
+            "ClientMessage",
+            "ServerMessage",
+            "GatewayEvent",
+            "TaskUpdate",
+        ] {
+            assert!(defs.contains_key(name), "missing definition {}", name);
+        }
+        assert!(breaking_changes(&schema, &schema).is_empty());
+    }
+
+    #[test]
+    fn test_added_optional_field_and_variant_are_compatible() {
+        let old = tagged(json!({ "oneOf": [
+            variant("Ping", json!({ "at": { "type": "string" } }), &["at"]),
+        ]}));
+        let new = tagged(json!({ "oneOf": [
+            variant("Ping", json!({
+                "at": { "type": "string" },
+                "note": { "type": ["string", "null"] },
+            }), &["at"]),
+            variant("Pong", json!({}), &[]),
+        ]}));
+        assert!(breaking_changes(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn test_removed_renamed_retyped_and_required_fields_break() {
+        let old = tagged(json!({ "oneOf": [
+            variant("Ping", json!({
+                "at": { "type": "string" },
+                "count": { "type": "integer" },
+            }), &["at"]),
+            variant("Status", json!({}), &[]),
+        ]}));
+        let new = tagged(json!({ "oneOf": [
+            variant("Ping", json!({
+                "timestamp": { "type": "string" },
+                "count": { "type": "string" },
+                "extra": { "type": "string" },
+            }), &["timestamp", "extra"]),
+            variant("GetStatus", json!({}), &[]),
+        ]}));
+        let breaks = breaking_changes(&old, &new);
+        let expected = [
+            "ClientMessage.Ping: field `at` was removed or renamed",
+            "ClientMessage.Ping.count: type changed from integer to string",
+            "ClientMessage.Ping: new required field `timestamp`",
+            "ClientMessage.Ping: new required field `extra`",
+            "ClientMessage: variant `Status` was removed or renamed",
+        ];
+        for line in expected {
+            assert!(
+                breaks.iter().any(|b| b == line),
+                "missing {:?} in {:#?}",
+                line,
+                breaks
+            );
+        }
+        assert_eq!(breaks.len(), expected.len(), "{:#?}", breaks);
+    }
+
+    #[test]
+    fn test_removed_enum_value_breaks() {
+        let old = tagged(json!({ "type": "string", "enum": ["status", "tasks"] }));
+        let new = tagged(json!({ "type": "string", "enum": ["status", "tasks", "admin"] }));
+        assert!(breaking_changes(&old, &new).is_empty());
+        assert_eq!(
+            breaking_changes(&new, &old),
+            vec!["ClientMessage: value \"admin\" was removed or renamed"]
+        );
+    }
+
+    #[test]
+    fn test_typescript_declarations() {
+        let ts = protocol_typescript(&protocol_schema());
+        assert!(ts.contains(&format!(
+            "export declare const PROTOCOL_VERSION: \"{}\";",
+            PROTOCOL_VERSION
+        )));
+        assert!(ts.contains("export type ClientMessage ="));
+        assert!(ts.contains("export type ServerMessage ="));
+        assert!(ts.contains("type: \"Authenticate\";"));
+        assert!(ts.contains("protocol_version?: string | null;"));
+        assert!(ts.contains("event: GatewayEvent;"));
+    }
+}
@@ -0,0 +1,836 @@
+//! PTY sessions for interactive commands.
+//!
+//! When the agent flags a shell command as interactive (a `sudo` prompt, an
+//! interactive rebase, a REPL), it runs in a pseudo-terminal owned by the
+//! gateway's [`PtyManager`] instead of with piped output. Output is broadcast
+//! on the [`EventTopic::Terminal`](super::EventTopic::Terminal) topic to the
+//! connections attached to the session, and attached clients holding the
+//! `tasks` scope can type into it.
+//!
+//! - Attaching takes a single-use stream token from
+//!   [`PtyManager::open_stream`], bound to one session and carrying the
+//!   requester's scope; a `read` token can watch but not type.
+//! - At most `max_sessions` PTYs run at once, and a session without input or
+//!   output for `idle_timeout_secs` is killed.
+//! - Detaching (or closing the browser tab) leaves the process running; only
+//!   [`PtyManager::kill`] ends it early.
+//! - Output passes through [`redact_secrets`] before it is broadcast, kept as
+//!   scrollback, or written to the session journal
+//!   (`<journal_dir>/<session>.log`). Input is journaled line by line, and
+//!   input typed at a password prompt is journaled as `[hidden input]`.
+
+use super::auth::AuthScope;
+use super::events::GatewayEvent;
+use crate::sanitize::{redact_secrets, strip_ansi_escapes};
+use chrono::{DateTime, Utc};
+use portable_pty::{ChildKiller, CommandBuilder, MasterPty, PtySize, native_pty_system};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use uuid::Uuid;
+
+/// A prompt whose answer must not be journaled.
+static PASSWORD_PROMPT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(password|passphrase|passcode|pin)\b[^:\n]*:\s*$")
+        .expect("valid password prompt regex")
+});
+
+/// Exited sessions kept for listing once the running ones fill the limit.
+const MAX_FINISHED_SESSIONS: usize = 16;
+
+/// Limits for gateway PTY sessions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PtyConfig {
+    /// Maximum PTY sessions running at once.
+    pub max_sessions: usize,
+    /// Kill a session after this long without input or output (0 = never).
+    pub idle_timeout_secs: u64,
+    /// Recent output replayed to a client when it attaches.
+    pub scrollback_bytes: usize,
+    /// How long an unredeemed stream token stays valid.
+    pub stream_token_ttl_secs: u64,
+}
+
+impl Default for PtyConfig {
+    fn default() -> Self {
+        Self {
+            max_sessions: 4,
+            idle_timeout_secs: 900,
+            scrollback_bytes: 64 * 1024,
+            stream_token_ttl_secs: 60,
+        }
+    }
+}
+
+/// Errors from PTY session operations.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PtyError {
+    #[error("PTY session limit reached ({max} running)")]
+    TooManySessions { max: usize },
+    #[error("PTY session {id} not found")]
+    NotFound { id: Uuid },
+    #[error("PTY session {id} has exited")]
+    Exited { id: Uuid },
+    #[error("Invalid or expired PTY stream token")]
+    InvalidToken,
+    #[error("Failed to start PTY: {0}")]
+    Spawn(String),
+    #[error("PTY I/O failed: {0}")]
+    Io(String),
+}
+
+/// Why a PTY session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PtyExitReason {
+    /// The command exited on its own.
+    Exited,
+    /// A user killed it explicitly.
+    Killed,
+    /// It produced no output and received no input for the idle timeout.
+    IdleTimeout,
+}
+
+/// How a PTY session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PtyExit {
+    pub exit_code: Option<i32>,
+    pub reason: PtyExitReason,
+}
+
+/// Public description of a PTY session.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PtySessionInfo {
+    pub id: Uuid,
+    pub command: String,
+    pub cwd: PathBuf,
+    /// Why the agent ran the command interactively.
+    pub reason: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub cols: u16,
+    pub rows: u16,
+    /// Set once the command has exited.
+    pub exit: Option<PtyExit>,
+}
+
+/// A command to run in a new PTY session.
+#[derive(Debug, Clone)]
+pub struct PtySpawn {
+    /// Shell command, run with `sh -c`.
+    pub command: String,
+    pub cwd: PathBuf,
+    pub reason: Option<String>,
+    /// Directory for the session journal; `None` disables journaling.
+    pub journal_dir: Option<PathBuf>,
+    pub cols: u16,
+    pub rows: u16,
+}
+
+/// A single-use credential for attaching to one PTY session.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PtyStreamToken {
+    pub session_id: Uuid,
+    pub token: String,
+    /// Whether the holder may type into and kill the session.
+    pub writable: bool,
+    pub expires_at: DateTime<Utc>,
+}
+
+struct StreamGrant {
+    session_id: Uuid,
+    scope: AuthScope,
+    expires: Instant,
+    /// Connection that redeemed the token.
+    attached: Option<Uuid>,
+}
+
+struct PtySession {
+    info: PtySessionInfo,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+    scrollback: String,
+    /// Output bytes of a UTF-8 character split across reads.
+    pending: Vec<u8>,
+    journal: PtyJournal,
+    last_activity: Instant,
+    kill_reason: Option<PtyExitReason>,
+    exit: watch::Sender<Option<PtyExit>>,
+}
+
+/// Owns the gateway's PTY sessions and their stream tokens.
+pub struct PtyManager {
+    config: PtyConfig,
+    events: broadcast::Sender<GatewayEvent>,
+    sessions: Mutex<HashMap<Uuid, PtySession>>,
+    grants: Mutex<HashMap<String, StreamGrant>>,
+}
+
+impl std::fmt::Debug for PtyManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PtyManager")
+            .field("config", &self.config)
+            .field("running", &self.running_count())
+            .finish()
+    }
+}
+
+impl PtyManager {
+    /// A manager broadcasting session events on `events`.
+    pub fn new(config: PtyConfig, events: broadcast::Sender<GatewayEvent>) -> Self {
+        Self {
+            config,
+            events,
+            sessions: Mutex::new(HashMap::new()),
+            grants: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn sessions(&self) -> MutexGuard<'_, HashMap<Uuid, PtySession>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn grants(&self) -> MutexGuard<'_, HashMap<String, StreamGrant>> {
+        self.grants.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The configured limits.
+    pub fn config(&self) -> &PtyConfig {
+        &self.config
+    }
+
+    /// Number of sessions whose command is still running.
+    pub fn running_count(&self) -> usize {
+        self.sessions()
+            .values()
+            .filter(|s| s.info.exit.is_none())
+            .count()
+    }
+
+    /// All known sessions, oldest first.
+    pub fn list(&self) -> Vec<PtySessionInfo> {
+        let mut sessions: Vec<_> = self.sessions().values().map(|s| s.info.clone()).collect();
+        sessions.sort_by_key(|s| s.started_at);
+        sessions
+    }
+
+    /// Describe one session.
+    pub fn get(&self, id: &Uuid) -> Option<PtySessionInfo> {
+        self.sessions().get(id).map(|s| s.info.clone())
+    }
+
+    /// Start `spawn.command` in a new PTY and announce it with
+    /// [`GatewayEvent::PtyOpened`]. Must be called within a tokio runtime.
+    pub fn spawn(self: &Arc<Self>, spawn: PtySpawn) -> Result<PtySessionInfo, PtyError> {
+        let mut sessions = self.sessions();
+        let running = sessions.values().filter(|s| s.info.exit.is_none()).count();
+        if running >= self.config.max_sessions {
+            return Err(PtyError::TooManySessions {
+                max: self.config.max_sessions,
+            });
+        }
+
+        let size = PtySize {
+            rows: spawn.rows.max(1),
+            cols: spawn.cols.max(1),
+            pixel_width: 0,
+            pixel_height: 0,
+        };
+        let pair = native_pty_system()
+            .openpty(size)
+            .map_err(|e| PtyError::Spawn(e.to_string()))?;
+        let mut cmd = CommandBuilder::new("sh");
+        cmd.args(["-c", spawn.command.as_str()]);
+        cmd.cwd(&spawn.cwd);
+        cmd.env("TERM", "xterm-256color");
+        let mut child = pair
+            .slave
+            .spawn_command(cmd)
+            .map_err(|e| PtyError::Spawn(e.to_string()))?;
+        drop(pair.slave);
+        let killer = child.clone_killer();
+        let reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| PtyError::Spawn(e.to_string()))?;
+        let writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| PtyError::Spawn(e.to_string()))?;
+
+        let id = Uuid::new_v4();
+        let info = PtySessionInfo {
+            id,
+            command: spawn.command.clone(),
+            cwd: spawn.cwd.clone(),
+            reason: spawn.reason.clone(),
+            started_at: Utc::now(),
+            cols: size.cols,
+            rows: size.rows,
+            exit: None,
+        };
+        let journal = PtyJournal::open(spawn.journal_dir.as_deref(), &info);
+        sessions.insert(
+            id,
+            PtySession {
+                info: info.clone(),
+                master: pair.master,
+                writer,
+                killer,
+                scrollback: String::new(),
+                pending: Vec::new(),
+                journal,
+                last_activity: Instant::now(),
+                kill_reason: None,
+                exit: watch::channel(None).0,
+            },
+        );
+        prune_finished(&mut sessions);
+        drop(sessions);
+
+        let (output_tx, output_rx) = mpsc::unbounded_channel();
+        std::thread::spawn(move || read_output(reader, output_tx));
+        let (exit_tx, exit_rx) = oneshot::channel();
+        std::thread::spawn(move || {
+            let code = child.wait().ok().map(|status| status.exit_code() as i32);
+            let _ = exit_tx.send(code);
+        });
+        tokio::spawn(pump(Arc::clone(self), id, output_rx, exit_rx));
+
+        let _ = self.events.send(GatewayEvent::PtyOpened {
+            session: info.clone(),
+        });
+        Ok(info)
+    }
+
+    /// Send keystrokes to a running session.
+    pub fn write(&self, id: &Uuid, data: &str) -> Result<(), PtyError> {
+        let mut sessions = self.sessions();
+        let session = sessions.get_mut(id).ok_or(PtyError::NotFound { id: *id })?;
+        if session.info.exit.is_some() {
+            return Err(PtyError::Exited { id: *id });
+        }
+        session
+            .writer
+            .write_all(data.as_bytes())
+            .and_then(|_| session.writer.flush())
+            .map_err(|e| PtyError::Io(e.to_string()))?;
+        session.last_activity = Instant::now();
+        session.journal.input(data);
+        Ok(())
+    }
+
+    /// Change the terminal size of a running session.
+    pub fn resize(&self, id: &Uuid, cols: u16, rows: u16) -> Result<(), PtyError> {
+        let mut sessions = self.sessions();
+        let session = sessions.get_mut(id).ok_or(PtyError::NotFound { id: *id })?;
+        let size = PtySize {
+            rows: rows.max(1),
+            cols: cols.max(1),
+            pixel_width: 0,
+            pixel_height: 0,
+        };
+        session
+            .master
+            .resize(size)
+            .map_err(|e| PtyError::Io(e.to_string()))?;
+        session.info.cols = size.cols;
+        session.info.rows = size.rows;
+        Ok(())
+    }
+
+    /// Kill a running session at a user's request.
+    pub fn kill(&self, id: &Uuid) -> Result<(), PtyError> {
+        self.terminate(id, PtyExitReason::Killed)
+    }
+
+    fn terminate(&self, id: &Uuid, reason: PtyExitReason) -> Result<(), PtyError> {
+        let mut sessions = self.sessions();
+        let session = sessions.get_mut(id).ok_or(PtyError::NotFound { id: *id })?;
+        if session.info.exit.is_some() {
+            return Err(PtyError::Exited { id: *id });
+        }
+        if session.kill_reason.is_none() {
+            session.kill_reason = Some(reason);
+        }
+        session
+            .killer
+            .kill()
+            .map_err(|e| PtyError::Io(e.to_string()))
+    }
+
+    /// Recent (redacted) output, as replayed to attaching clients.
+    pub fn scrollback(&self, id: &Uuid) -> Result<String, PtyError> {
+        self.sessions()
+            .get(id)
+            .map(|s| s.scrollback.clone())
+            .ok_or(PtyError::NotFound { id: *id })
+    }
+
+    /// Recent output as plain text: redacted, with escape sequences removed.
+    pub fn transcript(&self, id: &Uuid) -> Result<String, PtyError> {
+        let scrollback = self.scrollback(id)?;
+        Ok(strip_ansi_escapes(&scrollback.replace("\r\n", "\n")))
+    }
+
+    /// Wait up to `timeout` for a session to exit. Returns `None` if it is
+    /// still running.
+    pub async fn wait(&self, id: &Uuid, timeout: Duration) -> Result<Option<PtyExit>, PtyError> {
+        let mut exit = self
+            .sessions()
+            .get(id)
+            .map(|s| s.exit.subscribe())
+            .ok_or(PtyError::NotFound { id: *id })?;
+        match tokio::time::timeout(timeout, exit.wait_for(Option::is_some)).await {
+            Ok(Ok(exit)) => Ok(*exit),
+            Ok(Err(_)) => Err(PtyError::NotFound { id: *id }),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Issue a single-use token for attaching to `session_id` with `scope`.
+    pub fn open_stream(
+        &self,
+        session_id: Uuid,
+        scope: AuthScope,
+    ) -> Result<PtyStreamToken, PtyError> {
+        if !self.sessions().contains_key(&session_id) {
+            return Err(PtyError::NotFound { id: session_id });
+        }
+        let ttl = Duration::from_secs(self.config.stream_token_ttl_secs.max(1));
+        let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let mut grants = self.grants();
+        let now = Instant::now();
+        grants.retain(|_, g| g.attached.is_some() || g.expires > now);
+        grants.insert(
+            token.clone(),
+            StreamGrant {
+                session_id,
+                scope,
+                expires: now + ttl,
+                attached: None,
+            },
+        );
+        Ok(PtyStreamToken {
+            session_id,
+            token,
+            writable: scope >= AuthScope::Tasks,
+            expires_at: Utc::now() + chrono::Duration::seconds(ttl.as_secs() as i64),
+        })
+    }
+
+    /// Redeem a stream token for `connection_id`. Returns the session and
+    /// the scope the token carries.
+    pub fn redeem(&self, token: &str, connection_id: Uuid) -> Result<(Uuid, AuthScope), PtyError> {
+        let mut grants = self.grants();
+        let grant = grants.get_mut(token).ok_or(PtyError::InvalidToken)?;
+        if grant.attached.is_some() || grant.expires <= Instant::now() {
+            return Err(PtyError::InvalidToken);
+        }
+        grant.attached = Some(connection_id);
+        Ok((grant.session_id, grant.scope))
+    }
+
+    /// Revoke a stream token. Returns its session and the connection that
+    /// redeemed it, if any, so the caller can detach that connection. The
+    /// session itself keeps running.
+    pub fn close_stream(&self, token: &str) -> Option<(Uuid, Option<Uuid>)> {
+        self.grants()
+            .remove(token)
+            .map(|g| (g.session_id, g.attached))
+    }
+
+    fn record_output(&self, id: Uuid, bytes: &[u8]) {
+        let mut sessions = self.sessions();
+        let Some(session) = sessions.get_mut(&id) else {
+            return;
+        };
+        session.pending.extend_from_slice(bytes);
+        let text = take_utf8(&mut session.pending);
+        if text.is_empty() {
+            return;
+        }
+        let data = redact_secrets(&text);
+        session.last_activity = Instant::now();
+        session.journal.output(&data);
+        session.scrollback.push_str(&data);
+        trim_front(&mut session.scrollback, self.config.scrollback_bytes);
+        let _ = self.events.send(GatewayEvent::PtyOutput {
+            session_id: id,
+            data,
+        });
+    }
+
+    fn check_idle(&self, id: Uuid) {
+        if self.config.idle_timeout_secs == 0 {
+            return;
+        }
+        let idle = self
+            .sessions()
+            .get(&id)
+            .is_some_and(|s| s.last_activity.elapsed().as_secs() >= self.config.idle_timeout_secs);
+        if idle {
+            tracing::info!(session = %id, "Killing idle PTY session");
+            let _ = self.terminate(&id, PtyExitReason::IdleTimeout);
+        }
+    }
+
+    fn finish(&self, id: Uuid, exit_code: Option<i32>) {
+        let exit = {
+            let mut sessions = self.sessions();
+            let Some(session) = sessions.get_mut(&id) else {
+                return;
+            };
+            let exit = PtyExit {
+                exit_code,
+                reason: session.kill_reason.unwrap_or(PtyExitReason::Exited),
+            };
+            session.info.exit = Some(exit);
+            session.journal.close(&exit);
+            session.exit.send_replace(Some(exit));
+            exit
+        };
+        self.grants().retain(|_, g| g.session_id != id);
+        let _ = self.events.send(GatewayEvent::PtyExited {
+            session_id: id,
+            exit_code: exit.exit_code,
+            reason: exit.reason,
+        });
+    }
+}
+
+/// Forward PTY output to the pump until the PTY closes.
+fn read_output(mut reader: Box<dyn Read + Send>, tx: mpsc::UnboundedSender<Vec<u8>>) {
+    let mut buf = [0u8; 4096];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                if tx.send(buf[..n].to_vec()).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Record a session's output, enforce its idle timeout and report its exit.
+async fn pump(
+    manager: Arc<PtyManager>,
+    id: Uuid,
+    mut output: mpsc::UnboundedReceiver<Vec<u8>>,
+    mut exit: oneshot::Receiver<Option<i32>>,
+) {
+    let mut idle_check = tokio::time::interval(Duration::from_secs(1));
+    let exit_code = loop {
+        tokio::select! {
+            Some(bytes) = output.recv() => manager.record_output(id, &bytes),
+            code = &mut exit => {
+                // Give the reader a moment to deliver the final output.
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                while let Ok(bytes) = output.try_recv() {
+                    manager.record_output(id, &bytes);
+                }
+                break code.ok().flatten();
+            }
+            _ = idle_check.tick() => manager.check_idle(id),
+        }
+    };
+    manager.finish(id, exit_code);
+}
+
+/// Take the longest valid UTF-8 prefix of `pending`, leaving an incomplete
+/// trailing character for the next read. Invalid bytes become U+FFFD.
+fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let valid = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => pending.len(),
+    };
+    let rest = pending.split_off(valid);
+    let text = String::from_utf8_lossy(pending).into_owned();
+    *pending = rest;
+    text
+}
+
+/// Drop whole characters from the front of `text` until it fits in `max` bytes.
+fn trim_front(text: &mut String, max: usize) {
+    if text.len() <= max {
+        return;
+    }
+    let mut cut = text.len() - max;
+    while !text.is_char_boundary(cut) {
+        cut += 1;
+    }
+    text.drain(..cut);
+}
+
+/// Keep the newest sessions when exited ones accumulate.
+fn prune_finished(sessions: &mut HashMap<Uuid, PtySession>) {
+    let mut finished: Vec<(Uuid, DateTime<Utc>)> = sessions
+        .values()
+        .filter(|s| s.info.exit.is_some())
+        .map(|s| (s.info.id, s.info.started_at))
+        .collect();
+    if finished.len() <= MAX_FINISHED_SESSIONS {
+        return;
+    }
+    finished.sort_by_key(|(_, started)| *started);
+    for (id, _) in &finished[..finished.len() - MAX_FINISHED_SESSIONS] {
+        sessions.remove(id);
+    }
+}
+
+/// Line-buffered JSONL record of a session's redacted I/O.
+struct PtyJournal {
+    file: Option<std::fs::File>,
+    output_line: String,
+    input_line: String,
+    /// The last output line is a password prompt.
+    hide_input: bool,
+    in_private_key: bool,
+}
+
+impl PtyJournal {
+    fn open(dir: Option<&std::path::Path>, info: &PtySessionInfo) -> Self {
+        let file = dir.and_then(|dir| {
+            let opened = std::fs::create_dir_all(dir).and_then(|_| {
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(dir.join(format!("{}.log", info.id)))
+            });
+            match opened {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to open PTY session journal");
+                    None
+                }
+            }
+        });
+        let mut journal = Self {
+            file,
+            output_line: String::new(),
+            input_line: String::new(),
+            hide_input: false,
+            in_private_key: false,
+        };
+        journal.write("start", &redact_secrets(&info.command));
+        journal
+    }
+
+    fn write(&mut self, stream: &str, data: &str) {
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        let entry = serde_json::json!({
+            "ts": Utc::now().to_rfc3339(),
+            "stream": stream,
+            "data": data,
+        });
+        if writeln!(file, "{}", entry).is_err() {
+            self.file = None;
+        }
+    }
+
+    /// Record already-redacted output.
+    fn output(&mut self, data: &str) {
+        self.output_line.push_str(data);
+        while let Some(end) = self.output_line.find('\n') {
+            let line: String = self.output_line.drain(..=end).collect();
+            self.output_complete_line(&line);
+        }
+        let partial = strip_ansi_escapes(&self.output_line);
+        self.hide_input = PASSWORD_PROMPT.is_match(&partial);
+    }
+
+    fn output_complete_line(&mut self, line: &str) {
+        let line = strip_ansi_escapes(line.trim_end_matches(['\r', '\n']));
+        // Only the header of a private key matches the secret patterns, so
+        // drop the body lines that follow it.
+        if self.in_private_key {
+            if line.contains("-----END") {
+                self.in_private_key = false;
+            }
+            return;
+        }
+        if line.contains("-----BEGIN") && line.contains("PRIVATE KEY-----") {
+            self.in_private_key = true;
+        }
+        self.write("out", &line);
+    }
+
+    /// Record raw keystrokes, one entry per entered line.
+    fn input(&mut self, data: &str) {
+        for ch in data.chars() {
+            match ch {
+                '\r' | '\n' => {
+                    let line = if self.hide_input {
+                        "[hidden input]".to_string()
+                    } else {
+                        redact_secrets(&self.input_line)
+                    };
+                    self.input_line.clear();
+                    self.hide_input = false;
+                    self.write("in", &line);
+                }
+                '\u{7f}' | '\u{8}' => {
+                    self.input_line.pop();
+                }
+                c if c.is_control() => {}
+                c => self.input_line.push(c),
+            }
+        }
+    }
+
+    fn close(&mut self, exit: &PtyExit) {
+        if !self.output_line.is_empty() {
+            let line = std::mem::take(&mut self.output_line);
+            self.output_complete_line(&line);
+        }
+        let summary = serde_json::to_string(exit).unwrap_or_default();
+        self.write("exit", &summary);
+        self.file = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(config: PtyConfig) -> (Arc<PtyManager>, broadcast::Receiver<GatewayEvent>) {
+        let (tx, rx) = broadcast::channel(256);
+        (Arc::new(PtyManager::new(config, tx)), rx)
+    }
+
+    fn spawn_request(command: &str, journal_dir: Option<PathBuf>) -> PtySpawn {
+        PtySpawn {
+            command: command.into(),
+            cwd: std::env::temp_dir(),
+            reason: Some("test".into()),
+            journal_dir,
+            cols: 80,
+            rows: 24,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interactive_input_and_journal_redaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pty, _events) = manager(PtyConfig::default());
+        let info = pty
+            .spawn(spawn_request(
+                "printf 'Password: '; stty -echo; read secret; stty echo; \
+                 read line; echo \"got $line\"",
+                Some(dir.path().to_path_buf()),
+            ))
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        pty.write(&info.id, "hunter2\n").unwrap();
+        pty.write(&info.id, "token=abc123\n").unwrap();
+        let exit = pty
+            .wait(&info.id, Duration::from_secs(10))
+            .await
+            .unwrap()
+            .expect("command exits");
+        assert_eq!(exit.exit_code, Some(0));
+        assert_eq!(exit.reason, PtyExitReason::Exited);
+
+        let transcript = pty.transcript(&info.id).unwrap();
+        assert!(transcript.contains("got token=[REDACTED]"), "{transcript}");
+
+        let journal = std::fs::read_to_string(dir.path().join(format!("{}.log", info.id))).unwrap();
+        assert!(journal.contains("[hidden input]"));
+        assert!(!journal.contains("hunter2"));
+        assert!(!journal.contains("abc123"));
+        assert!(journal.contains(r#""stream":"exit""#));
+    }
+
+    #[tokio::test]
+    async fn test_session_limit_and_kill() {
+        let config = PtyConfig {
+            max_sessions: 1,
+            ..Default::default()
+        };
+        let (pty, mut events) = manager(config);
+        let info = pty.spawn(spawn_request("sleep 30", None)).unwrap();
+        assert!(matches!(
+            events.recv().await,
+            Ok(GatewayEvent::PtyOpened { .. })
+        ));
+        assert_eq!(
+            pty.spawn(spawn_request("true", None)).unwrap_err(),
+            PtyError::TooManySessions { max: 1 }
+        );
+
+        pty.kill(&info.id).unwrap();
+        let exit = pty
+            .wait(&info.id, Duration::from_secs(10))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(exit.reason, PtyExitReason::Killed);
+        assert_eq!(pty.running_count(), 0);
+        assert!(pty.spawn(spawn_request("true", None)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_idle_session_is_killed() {
+        let config = PtyConfig {
+            idle_timeout_secs: 1,
+            ..Default::default()
+        };
+        let (pty, _events) = manager(config);
+        let info = pty.spawn(spawn_request("sleep 30", None)).unwrap();
+        let exit = pty
+            .wait(&info.id, Duration::from_secs(10))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(exit.reason, PtyExitReason::IdleTimeout);
+    }
+
+    #[tokio::test]
+    async fn test_stream_tokens_are_single_use_and_scoped() {
+        let (pty, _events) = manager(PtyConfig::default());
+        let info = pty.spawn(spawn_request("sleep 30", None)).unwrap();
+        let conn = Uuid::new_v4();
+
+        let read = pty.open_stream(info.id, AuthScope::Read).unwrap();
+        assert!(!read.writable);
+        assert_eq!(
+            pty.redeem(&read.token, conn).unwrap(),
+            (info.id, AuthScope::Read)
+        );
+        assert_eq!(
+            pty.redeem(&read.token, conn).unwrap_err(),
+            PtyError::InvalidToken
+        );
+        assert_eq!(pty.close_stream(&read.token), Some((info.id, Some(conn))));
+
+        assert!(pty.open_stream(info.id, AuthScope::Tasks).unwrap().writable);
+        assert!(matches!(
+            pty.open_stream(Uuid::new_v4(), AuthScope::Tasks),
+            Err(PtyError::NotFound { .. })
+        ));
+        pty.kill(&info.id).unwrap();
+    }
+
+    #[test]
+    fn test_take_utf8_keeps_split_characters() {
+        let mut pending = "h\u{e9}".as_bytes().to_vec();
+        let last = pending.pop().unwrap();
+        assert_eq!(take_utf8(&mut pending), "h");
+        pending.push(last);
+        assert_eq!(take_utf8(&mut pending), "\u{e9}");
+        assert!(pending.is_empty());
+    }
+}
@@ -0,0 +1,390 @@
+//! Configuration hot-reload for a running gateway.
+//!
+//! [`GatewayServer::reload`](super::GatewayServer::reload) compares a freshly
+//! loaded [`AgentConfig`] with the configuration the gateway is running.
+//! Settings that can change under live connections are applied in place:
+//! gateway auth tokens and connection/task limits, provider rate limits, and
+//! any section a host registered a [`ReloadHandler`] for (the CLI registers
+//! `logging`). Everything else — the listen address, channels, providers — is
+//! reported as deferred until restart and the running values stay in effect.
+//! Token values never appear in a report, only the names of changed settings.
+
+use super::GatewayConfig;
+use crate::config::AgentConfig;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Gateway settings applied without restarting the listener.
+const LIVE_GATEWAY_SETTINGS: &[&str] = &[
+    "auth_tokens",
+    "task_tokens",
+    "max_connections",
+    "session_timeout_secs",
+    "event_queue_capacity",
+    "max_concurrent_tasks",
+    "max_queued_tasks",
+    "pairing",
+    "ping_interval_secs",
+];
+
+/// Loads the current configuration from its source, e.g. the config files.
+pub type ConfigLoader = Box<dyn Fn() -> Result<AgentConfig, String> + Send + Sync>;
+
+/// Applies one top-level configuration section while the gateway runs.
+pub trait ReloadHandler: Send + Sync {
+    /// The `AgentConfig` field this handler owns, e.g. `"logging"`.
+    fn section(&self) -> &str;
+    /// Apply the section from `config`. On error the previous value stays in effect.
+    fn apply(&self, config: &AgentConfig) -> Result<(), String>;
+}
+
+/// Outcome of a configuration reload.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ReloadReport {
+    pub requested_at: DateTime<Utc>,
+    /// Settings now in effect, e.g. `gateway.auth_tokens`.
+    pub applied: Vec<String>,
+    /// Changed settings that only take effect after a restart.
+    pub deferred: Vec<String>,
+    /// Settings that failed to apply, or a load error.
+    pub errors: Vec<String>,
+}
+
+impl ReloadReport {
+    pub(crate) fn new() -> Self {
+        Self {
+            requested_at: Utc::now(),
+            ..Default::default()
+        }
+    }
+
+    /// A report for a reload that failed before anything was compared.
+    pub(crate) fn failed(error: impl Into<String>) -> Self {
+        let mut report = Self::new();
+        report.errors.push(error.into());
+        report
+    }
+
+    /// One-line summary for logs and the CLI.
+    pub fn summary(&self) -> String {
+        if !self.errors.is_empty() && self.applied.is_empty() {
+            return format!("reload failed: {}", self.errors.join("; "));
+        }
+        let mut parts = Vec::new();
+        if self.applied.is_empty() && self.deferred.is_empty() {
+            parts.push("no changes".to_string());
+        }
+        if !self.applied.is_empty() {
+            parts.push(format!("applied {}", self.applied.join(", ")));
+        }
+        if !self.deferred.is_empty() {
+            parts.push(format!("restart required for {}", self.deferred.join(", ")));
+        }
+        if !self.errors.is_empty() {
+            parts.push(format!("errors: {}", self.errors.join("; ")));
+        }
+        parts.join("; ")
+    }
+}
+
+/// Reload bookkeeping held by the gateway.
+pub(crate) struct ReloadState {
+    /// Configuration at startup: what restart-only settings are running with.
+    startup: Value,
+    /// Configuration from the last reload: the baseline for live settings.
+    current: Value,
+    handlers: Vec<Box<dyn ReloadHandler>>,
+    loader: Option<ConfigLoader>,
+    last: Option<ReloadReport>,
+}
+
+impl std::fmt::Debug for ReloadState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReloadState")
+            .field("handlers", &self.handlers.len())
+            .field("has_loader", &self.loader.is_some())
+            .field("last", &self.last)
+            .finish()
+    }
+}
+
+impl ReloadState {
+    pub(crate) fn new(gateway: &GatewayConfig) -> Self {
+        let baseline = to_value(&AgentConfig {
+            gateway: Some(gateway.clone()),
+            ..Default::default()
+        });
+        Self {
+            startup: baseline.clone(),
+            current: baseline,
+            handlers: Vec::new(),
+            loader: None,
+            last: None,
+        }
+    }
+
+    pub(crate) fn set_baseline(&mut self, config: &AgentConfig) {
+        self.startup = to_value(config);
+        self.current = self.startup.clone();
+    }
+
+    pub(crate) fn add_handler(&mut self, handler: Box<dyn ReloadHandler>) {
+        self.handlers.push(handler);
+    }
+
+    pub(crate) fn set_loader(&mut self, loader: ConfigLoader) {
+        self.loader = Some(loader);
+    }
+
+    pub(crate) fn load(&self) -> Result<AgentConfig, String> {
+        match &self.loader {
+            Some(loader) => loader(),
+            None => Err("No configuration source is attached to this gateway".to_string()),
+        }
+    }
+
+    pub(crate) fn last(&self) -> Option<&ReloadReport> {
+        self.last.as_ref()
+    }
+
+    pub(crate) fn record(&mut self, report: ReloadReport) {
+        self.last = Some(report);
+    }
+
+    /// Compare `config` with the running configuration, apply live settings
+    /// (gateway settings onto `gateway`), and report what changed.
+    pub(crate) fn apply(
+        &mut self,
+        config: &AgentConfig,
+        gateway: &mut GatewayConfig,
+    ) -> ReloadReport {
+        let mut report = ReloadReport::new();
+        let new = to_value(config);
+
+        for section in section_names(&[&self.startup, &self.current, &new]) {
+            let startup = field(&self.startup, &section);
+            let current = field(&self.current, &section);
+            let next = field(&new, &section);
+            match section.as_str() {
+                "gateway" => {
+                    // A missing [gateway] section leaves the running settings alone.
+                    if config.gateway.is_some() {
+                        diff_fields(&section, startup, current, next, &mut report, |name| {
+                            LIVE_GATEWAY_SETTINGS.contains(&name)
+                        });
+                    }
+                }
+                "llm" => {
+                    diff_fields(&section, startup, current, next, &mut report, |name| {
+                        name == "rate_limits"
+                    });
+                    if report.applied.iter().any(|s| s == "llm.rate_limits")
+                        && let Err(e) = crate::providers::limiter::reconfigure_limiter(&config.llm)
+                    {
+                        report.applied.retain(|s| s != "llm.rate_limits");
+                        report.errors.push(format!("llm.rate_limits: {e}"));
+                    }
+                }
+                _ => {
+                    if let Some(handler) = self.handlers.iter().find(|h| h.section() == section) {
+                        if current != next {
+                            match handler.apply(config) {
+                                Ok(()) => report.applied.push(section.clone()),
+                                Err(e) => report.errors.push(format!("{section}: {e}")),
+                            }
+                        }
+                    } else if startup != next {
+                        report.deferred.push(section.clone());
+                    }
+                }
+            }
+        }
+
+        if config.gateway.is_some() {
+            let next = config.gateway.clone().unwrap_or_default();
+            for setting in &report.applied {
+                match setting.strip_prefix("gateway.").unwrap_or_default() {
+                    "auth_tokens" => gateway.auth_tokens = next.auth_tokens.clone(),
+                    "task_tokens" => gateway.task_tokens = next.task_tokens.clone(),
+                    "max_connections" => gateway.max_connections = next.max_connections,
+                    "session_timeout_secs" => {
+                        gateway.session_timeout_secs = next.session_timeout_secs
+                    }
+                    "event_queue_capacity" => {
+                        gateway.event_queue_capacity = next.event_queue_capacity
+                    }
+                    "max_concurrent_tasks" => {
+                        gateway.max_concurrent_tasks = next.max_concurrent_tasks
+                    }
+                    "max_queued_tasks" => gateway.max_queued_tasks = next.max_queued_tasks,
+                    "pairing" => gateway.pairing = next.pairing.clone(),
+                    "ping_interval_secs" => gateway.ping_interval_secs = next.ping_interval_secs,
+                    _ => {}
+                }
+            }
+        }
+
+        // Failed sections keep their old baseline so the next reload retries them.
+        let mut current = new;
+        for error in &report.errors {
+            let section = error.split([':', '.']).next().unwrap_or_default();
+            if let Some(map) = current.as_object_mut() {
+                match self.current.get(section) {
+                    Some(old) => map.insert(section.to_string(), old.clone()),
+                    None => map.remove(section),
+                };
+            }
+        }
+        self.current = current;
+        report
+    }
+}
+
+fn to_value(config: &AgentConfig) -> Value {
+    serde_json::to_value(config).unwrap_or(Value::Null)
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> &'a Value {
+    value.get(name).unwrap_or(&Value::Null)
+}
+
+fn section_names(values: &[&Value]) -> BTreeSet<String> {
+    values
+        .iter()
+        .filter_map(|v| v.as_object())
+        .flat_map(|map| map.keys().cloned())
+        .collect()
+}
+
+/// Report the changed fields of one section. Live fields are compared with
+/// the last applied configuration; restart-only fields with the startup one,
+/// so reverting a deferred change clears it.
+fn diff_fields(
+    section: &str,
+    startup: &Value,
+    current: &Value,
+    next: &Value,
+    report: &mut ReloadReport,
+    is_live: impl Fn(&str) -> bool,
+) {
+    let names = section_names(&[startup, current, next]);
+    for name in names {
+        let next_field = field(next, &name);
+        if is_live(&name) {
+            if field(current, &name) != next_field {
+                report.applied.push(format!("{section}.{name}"));
+            }
+        } else if field(startup, &name) != next_field {
+            report.deferred.push(format!("{section}.{name}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    struct RecordingHandler(Arc<Mutex<Vec<Option<String>>>>);
+
+    impl ReloadHandler for RecordingHandler {
+        fn section(&self) -> &str {
+            "logging"
+        }
+
+        fn apply(&self, config: &AgentConfig) -> Result<(), String> {
+            let filter = config.logging.as_ref().and_then(|l| l.filter.clone());
+            if filter.as_deref() == Some("bad[") {
+                return Err("invalid filter".into());
+            }
+            self.0.lock().unwrap().push(filter);
+            Ok(())
+        }
+    }
+
+    fn config_with(gateway: GatewayConfig) -> AgentConfig {
+        AgentConfig {
+            gateway: Some(gateway),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_live_gateway_settings_apply_and_address_defers() {
+        let mut running = GatewayConfig::default();
+        let mut state = ReloadState::new(&running);
+        state.set_baseline(&config_with(running.clone()));
+
+        let mut next = running.clone();
+        next.auth_tokens = vec!["secret-token".into()];
+        next.max_connections = 3;
+        next.port = 9999;
+        let report = state.apply(&config_with(next), &mut running);
+
+        assert_eq!(
+            report.applied,
+            vec!["gateway.auth_tokens", "gateway.max_connections"]
+        );
+        assert_eq!(report.deferred, vec!["gateway.port"]);
+        assert!(report.errors.is_empty());
+        assert_eq!(running.auth_tokens, vec!["secret-token".to_string()]);
+        assert_eq!(running.max_connections, 3);
+        assert_eq!(running.port, 8080);
+        assert!(!report.summary().contains("secret-token"));
+
+        // Reloading the same file applies nothing but still reports the port.
+        let mut same = running.clone();
+        same.port = 9999;
+        let report = state.apply(&config_with(same), &mut running);
+        assert!(report.applied.is_empty());
+        assert_eq!(report.deferred, vec!["gateway.port"]);
+
+        // Reverting the port clears the deferred change.
+        let report = state.apply(&config_with(running.clone()), &mut running);
+        assert!(report.deferred.is_empty());
+        assert_eq!(report.summary(), "no changes");
+    }
+
+    #[test]
+    fn test_handler_sections_and_unhandled_sections() {
+        let mut running = GatewayConfig::default();
+        let mut state = ReloadState::new(&running);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        state.add_handler(Box::new(RecordingHandler(seen.clone())));
+
+        let mut config = config_with(running.clone());
+        config.logging = Some(crate::config::LoggingConfig {
+            filter: Some("debug".into()),
+        });
+        config.persona = Some("ops-sre".into());
+        let report = state.apply(&config, &mut running);
+        assert_eq!(report.applied, vec!["logging"]);
+        assert_eq!(report.deferred, vec!["persona"]);
+        assert_eq!(*seen.lock().unwrap(), vec![Some("debug".to_string())]);
+
+        // A failing handler is reported and retried on the next reload.
+        config.logging = Some(crate::config::LoggingConfig {
+            filter: Some("bad[".into()),
+        });
+        let report = state.apply(&config, &mut running);
+        assert_eq!(report.errors, vec!["logging: invalid filter"]);
+        assert!(report.summary().starts_with("reload failed"));
+        let report = state.apply(&config, &mut running);
+        assert_eq!(report.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_missing_gateway_section_keeps_running_settings() {
+        let mut running = GatewayConfig {
+            auth_tokens: vec!["keep".into()],
+            ..Default::default()
+        };
+        let mut state = ReloadState::new(&running);
+        let report = state.apply(&AgentConfig::default(), &mut running);
+        assert!(report.applied.is_empty());
+        assert_eq!(running.auth_tokens, vec!["keep".to_string()]);
+    }
+}
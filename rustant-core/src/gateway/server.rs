@@ -1,24 +1,56 @@
 //! WebSocket gateway server built on axum.
 
 use super::GatewayConfig;
-use super::auth::GatewayAuth;
-use super::connection::ConnectionManager;
+use super::approvals::ApprovalEscalator;
+use super::auth::{AuthScope, GatewayAuth};
+use super::connection::{ConnectionInfo, ConnectionManager, DisconnectReason};
+use super::devices::{
+    DeviceGrant, DevicePairingRequest, DeviceRegistry, PairedDevice, PairingError, PairingOffer,
+    qr_png,
+};
+use super::event_queue::EventQueue;
 use super::events::{ClientMessage, GatewayEvent, ServerMessage};
+use super::forms::{
+    ClosedForm, FormOutcome, FormReceipt, FormRegistry, FormResolution, FormSubmitError,
+    PendingForm,
+};
+use super::protocol::{PROTOCOL_HEADER, PROTOCOL_VERSION, version_mismatch};
+use super::pty::{PtyError, PtyManager, PtyStreamToken};
+use super::reload::{ConfigLoader, ReloadHandler, ReloadReport, ReloadState};
 use super::session::SessionManager;
+use super::tasks::{
+    CancelOutcome, TaskOptions, TaskQueue, TaskRequest, TaskRunner, TaskUpdate, dispatch_tasks,
+};
+use crate::approval_timeout::{ApprovalTimeoutConfig, ExpiredApproval, ResolvedPolicy};
+use crate::artifacts::ArtifactRecord;
+use crate::canvas::{FormSpec, bind_template, redact_submission, render_form_html_for};
+use crate::config::{AgentConfig, ApprovalMode};
+use crate::safety::AuditEvent;
+use crate::types::RiskLevel;
 use axum::{
-    Router,
+    Extension, Router,
     extract::{
-        Path, State,
+        ConnectInfo, Path, Query, State,
         ws::{Message as WsMessage, WebSocket, WebSocketUpgrade},
     },
-    http::StatusCode,
-    response::IntoResponse,
-    routing::{get, post},
+    http::{
+        HeaderMap, HeaderName, HeaderValue, StatusCode,
+        header::{AUTHORIZATION, CONTENT_TYPE},
+    },
+    middleware,
+    response::{Html, IntoResponse, Response},
+    routing::{delete, get, post},
 };
-use chrono::Utc;
+use base64::Engine;
+use chrono::{DateTime, Utc};
 use futures::SinkExt;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::{Mutex, broadcast};
+use std::time::Duration;
+use tokio::sync::{Mutex, broadcast, oneshot, watch};
 use uuid::Uuid;
 
 /// Provides channel and node status snapshots for the gateway.
@@ -32,6 +64,17 @@ pub trait StatusProvider: Send + Sync {
     fn node_statuses(&self) -> Vec<(String, String)>;
 }
 
+/// Lifecycle phase of a gateway, observed by every WebSocket connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownPhase {
+    /// Serving normally.
+    Running,
+    /// No new tasks; running ones have `grace_secs` to finish.
+    Draining { grace_secs: u64 },
+    /// Connections are being closed.
+    Closed,
+}
+
 /// Thread-safe shared gateway reference for axum handlers.
 pub type SharedGateway = Arc<Mutex<GatewayServer>>;
 
@@ -53,6 +96,57 @@ pub struct GatewayServer {
     config_json: String,
     /// Shared toggle state for voice/meeting sessions.
     toggle_state: Option<Arc<crate::voice::toggle::ToggleState>>,
+    /// Artifacts registered by the agent, oldest first.
+    artifacts: Vec<ArtifactRecord>,
+    /// Tasks submitted by clients, queued or running.
+    tasks: TaskQueue,
+    /// Executes submitted tasks; submissions are rejected until one is set.
+    task_runner: Option<Arc<dyn TaskRunner>>,
+    /// Agents blocked on an approval decision, keyed by approval ID.
+    approval_waiters: std::collections::HashMap<Uuid, oneshot::Sender<bool>>,
+    /// PTY sessions for interactive commands.
+    pty: Arc<PtyManager>,
+    /// Baseline, handlers and last result for configuration reloads.
+    reload: ReloadState,
+    /// Shutdown phase, watched by connections.
+    shutdown: watch::Sender<ShutdownPhase>,
+    /// Devices paired by QR code, and pairing offers awaiting a device.
+    devices: DeviceRegistry,
+    /// Connections to close, e.g. those of a revoked device, with the
+    /// reason told to the client.
+    disconnects: broadcast::Sender<(Uuid, String)>,
+    /// Recent security-relevant gateway events, oldest first.
+    audit: VecDeque<GatewayAuditEntry>,
+    /// JSONL file the audit entries are appended to.
+    audit_path: Option<PathBuf>,
+    /// Workspace tasks run in when they name none, for display.
+    default_workspace: Option<String>,
+    /// Scheduler state directory holding the cron job history.
+    cron_state_dir: Option<PathBuf>,
+    /// Workspace whose focus block is reported in the status.
+    focus_workspace: Option<PathBuf>,
+    /// Event store the cost report is read from.
+    event_store_path: Option<PathBuf>,
+    /// Monthly soft cap reported in the status.
+    cost_cap: Option<crate::event_store::CostCap>,
+    /// Approval mode the timeout policies are resolved for.
+    approval_mode: ApprovalMode,
+    /// What happens to approvals nobody decides in time.
+    approval_timeouts: ApprovalTimeoutConfig,
+    /// Delivers approvals whose timeout policy escalates.
+    approval_escalator: Option<Arc<dyn ApprovalEscalator>>,
+    /// Canvas forms accepting submissions, and the tasks waiting on them.
+    forms: FormRegistry,
+}
+
+/// Audit entries kept in memory for `/api/audit`.
+const MAX_AUDIT_ENTRIES: usize = 1000;
+
+/// A security-relevant gateway event, such as a device pairing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayAuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub event: AuditEvent,
 }
 
 /// A pending approval request awaiting user decision.
@@ -66,6 +160,14 @@ pub struct PendingApproval {
     pub description: String,
     /// Risk level string.
     pub risk_level: String,
+    /// When the approval was requested.
+    pub created_at: DateTime<Utc>,
+    /// When the current waiting window ends, if a timeout policy applies.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Whether it has been escalated to a channel.
+    pub escalated: bool,
+    /// The `[safety.approval_timeouts]` entry governing it.
+    pub policy: Option<String>,
 }
 
 impl std::fmt::Debug for GatewayServer {
@@ -85,6 +187,11 @@ impl GatewayServer {
         let connections = ConnectionManager::new(config.max_connections);
         let sessions = SessionManager::new();
         let (event_tx, _) = broadcast::channel(config.broadcast_capacity);
+        let tasks = TaskQueue::new(config.max_concurrent_tasks, config.max_queued_tasks);
+        let pty = Arc::new(PtyManager::new(config.pty.clone(), event_tx.clone()));
+        let reload = ReloadState::new(&config);
+        let (shutdown, _) = watch::channel(ShutdownPhase::Running);
+        let (disconnects, _) = broadcast::channel(16);
 
         Self {
             config,
@@ -99,7 +206,162 @@ impl GatewayServer {
             pending_approvals: std::collections::HashMap::new(),
             config_json: "{}".to_string(),
             toggle_state: None,
+            artifacts: Vec::new(),
+            tasks,
+            task_runner: None,
+            approval_waiters: std::collections::HashMap::new(),
+            pty,
+            reload,
+            shutdown,
+            devices: DeviceRegistry::new(),
+            disconnects,
+            audit: VecDeque::new(),
+            audit_path: None,
+            default_workspace: None,
+            cron_state_dir: None,
+            focus_workspace: None,
+            event_store_path: None,
+            cost_cap: None,
+            approval_mode: ApprovalMode::default(),
+            approval_timeouts: ApprovalTimeoutConfig::default(),
+            approval_escalator: None,
+            forms: FormRegistry::default(),
+        }
+    }
+
+    /// Keep paired devices (`devices.json`) and the gateway audit log
+    /// (`gateway-audit.jsonl`) in `dir`, loading devices paired earlier.
+    pub fn set_state_dir(&mut self, dir: &std::path::Path) -> Result<(), PairingError> {
+        std::fs::create_dir_all(dir).map_err(|e| PairingError::Store(e.to_string()))?;
+        self.devices = DeviceRegistry::open(dir.join("devices.json"))?;
+        self.audit_path = Some(dir.join("gateway-audit.jsonl"));
+        Ok(())
+    }
+
+    /// Create a single-use pairing offer for a device limited to `scope`.
+    pub fn create_pairing_offer(&mut self, scope: AuthScope) -> PairingOffer {
+        let base_url = match &self.config.pairing.public_url {
+            Some(url) => url.clone(),
+            None => format!("http://{}:{}", self.config.host, self.config.port),
+        };
+        let offer =
+            self.devices
+                .create_offer(scope, self.config.pairing.challenge_ttl_secs, &base_url);
+        self.record_audit(AuditEvent::DevicePairing {
+            action: "offered".to_string(),
+            device: offer.challenge.challenge_id.to_string(),
+            detail: format!(
+                "scope {}, expires {}",
+                scope,
+                offer.challenge.expires_at.to_rfc3339()
+            ),
+        });
+        offer
+    }
+
+    /// Complete a pairing offer and issue the device its token.
+    pub fn complete_pairing(
+        &mut self,
+        request: &DevicePairingRequest,
+    ) -> Result<(PairedDevice, DeviceGrant), PairingError> {
+        match self.devices.complete(request) {
+            Ok((device, grant)) => {
+                self.record_audit(AuditEvent::DevicePairing {
+                    action: "paired".to_string(),
+                    device: device.identity.device_name.clone(),
+                    detail: format!(
+                        "{} ({}), scope {}",
+                        device.identity.device_id, device.platform, device.scope
+                    ),
+                });
+                self.broadcast(GatewayEvent::DevicePaired {
+                    device: device.clone(),
+                });
+                Ok((device, grant))
+            }
+            Err(e) => {
+                self.record_audit(AuditEvent::DevicePairing {
+                    action: "rejected".to_string(),
+                    device: request.response.device_name.chars().take(64).collect(),
+                    detail: e.to_string(),
+                });
+                Err(e)
+            }
+        }
+    }
+
+    /// Devices paired by QR code.
+    pub fn paired_devices(&self) -> Vec<PairedDevice> {
+        self.devices.devices()
+    }
+
+    /// Revoke a paired device and close its connections. Returns `None` if
+    /// the device is unknown.
+    pub fn revoke_device(
+        &mut self,
+        device_id: &Uuid,
+    ) -> Result<Option<PairedDevice>, PairingError> {
+        let Some(device) = self.devices.revoke(device_id)? else {
+            return Ok(None);
+        };
+        self.record_audit(AuditEvent::DevicePairing {
+            action: "revoked".to_string(),
+            device: device.identity.device_name.clone(),
+            detail: device_id.to_string(),
+        });
+        for conn_id in self.connections.deauthenticate_device(device_id) {
+            let _ = self
+                .disconnects
+                .send((conn_id, "Device revoked".to_string()));
+        }
+        self.broadcast(GatewayEvent::DeviceRevoked {
+            device_id: *device_id,
+        });
+        Ok(Some(device))
+    }
+
+    /// Close a client's connection on an operator's request and audit it.
+    /// Returns the client's metadata, or `None` for an unknown connection.
+    pub fn disconnect_client(&mut self, connection_id: &Uuid) -> Option<ConnectionInfo> {
+        let client = self.connections.get(connection_id)?.clone();
+        self.record_audit(AuditEvent::ClientDisconnected {
+            connection_id: *connection_id,
+            client: client.label(),
+            detail: format!(
+                "from {}, {}",
+                client
+                    .remote_addr
+                    .map_or("unknown address".to_string(), |a| a.to_string()),
+                client.token_id.as_deref().unwrap_or("unauthenticated")
+            ),
+        });
+        let _ = self.disconnects.send((
+            *connection_id,
+            "Disconnected by the gateway operator".to_string(),
+        ));
+        Some(client)
+    }
+
+    /// Add an entry to the gateway audit log.
+    pub fn record_audit(&mut self, event: AuditEvent) {
+        let entry = GatewayAuditEntry {
+            timestamp: Utc::now(),
+            event,
+        };
+        if let Some(path) = &self.audit_path
+            && let Err(e) = append_audit(path, &entry)
+        {
+            tracing::warn!("Failed to write gateway audit log: {}", e);
         }
+        if self.audit.len() == MAX_AUDIT_ENTRIES {
+            self.audit.pop_front();
+        }
+        self.audit.push_back(entry);
+    }
+
+    /// Recent gateway audit entries, oldest first.
+    pub fn audit_entries(&self) -> &VecDeque<GatewayAuditEntry> {
+        &self.audit
     }
 
     /// Get a reference to the gateway configuration.
@@ -142,6 +404,11 @@ impl GatewayServer {
         self.event_tx.send(event).unwrap_or(0)
     }
 
+    /// Clone of the event sender, for progress reporting outside the lock.
+    pub(crate) fn event_sender(&self) -> broadcast::Sender<GatewayEvent> {
+        self.event_tx.clone()
+    }
+
     /// Uptime in seconds since the server was created.
     pub fn uptime_secs(&self) -> u64 {
         let elapsed = Utc::now() - self.started_at;
@@ -153,8 +420,331 @@ impl GatewayServer {
         self.status_provider = Some(provider);
     }
 
+    /// Set the runner that executes submitted tasks.
+    pub fn set_task_runner(&mut self, runner: Arc<dyn TaskRunner>) {
+        self.task_runner = Some(runner);
+    }
+
+    /// The configured task runner, if any.
+    pub fn task_runner(&self) -> Option<Arc<dyn TaskRunner>> {
+        self.task_runner.clone()
+    }
+
+    pub(crate) fn tasks_mut(&mut self) -> &mut TaskQueue {
+        &mut self.tasks
+    }
+
+    /// Number of submitted tasks currently running.
+    pub fn running_tasks(&self) -> usize {
+        self.tasks.running_count()
+    }
+
+    /// Number of submitted tasks waiting for a free slot.
+    pub fn queued_tasks(&self) -> usize {
+        self.tasks.queued_count()
+    }
+
+    /// Queue a task for the task runner and announce it to clients.
+    ///
+    /// Returns the task ID and its queue position. Call [`dispatch_tasks`]
+    /// afterwards to start it once a slot is free.
+    pub fn submit_task(
+        &mut self,
+        text: String,
+        workspace: Option<String>,
+        options: TaskOptions,
+        conn_id: Uuid,
+    ) -> Result<(Uuid, usize), String> {
+        if self.is_shutting_down() {
+            return Err("Gateway is shutting down; not accepting new tasks".to_string());
+        }
+        if self.task_runner.is_none() {
+            return Err("No task runner is attached to this gateway".to_string());
+        }
+        if text.trim().is_empty() {
+            return Err("Task text is empty".to_string());
+        }
+        let task_id = Uuid::new_v4();
+        let position = self.tasks.enqueue(TaskRequest {
+            task_id,
+            text: text.clone(),
+            workspace,
+            options,
+            connection_id: conn_id,
+        })?;
+        self.broadcast(GatewayEvent::TaskSubmitted {
+            task_id,
+            description: text,
+        });
+        self.broadcast(GatewayEvent::TaskUpdate {
+            task_id,
+            update: TaskUpdate::Accepted { position },
+        });
+        Ok((task_id, position))
+    }
+
+    /// Cancel a queued or running task. Returns `false` if it is unknown.
+    ///
+    /// Queued tasks complete immediately; running tasks complete once the
+    /// runner observes its cancellation token.
+    pub fn cancel_task(&mut self, task_id: &Uuid) -> bool {
+        match self.tasks.cancel(task_id) {
+            CancelOutcome::Dequeued => {
+                self.broadcast(GatewayEvent::TaskUpdate {
+                    task_id: *task_id,
+                    update: TaskUpdate::Cancelled,
+                });
+                self.broadcast(GatewayEvent::TaskCompleted {
+                    task_id: *task_id,
+                    success: false,
+                    summary: "Cancelled by client".to_string(),
+                });
+                true
+            }
+            CancelOutcome::Signalled => true,
+            CancelOutcome::NotFound => false,
+        }
+    }
+
+    /// The PTY session manager, shared with tools that run interactive commands.
+    pub fn pty(&self) -> Arc<PtyManager> {
+        Arc::clone(&self.pty)
+    }
+
+    /// Issue a stream token for attaching to a PTY session with `scope`.
+    pub fn open_pty_stream(
+        &self,
+        session_id: Uuid,
+        scope: AuthScope,
+    ) -> Result<PtyStreamToken, PtyError> {
+        self.pty.open_stream(session_id, scope)
+    }
+
+    /// Revoke a PTY stream token and detach the connection that used it.
+    /// The session's process keeps running. Returns `false` for unknown tokens.
+    pub fn close_pty_stream(&mut self, token: &str) -> bool {
+        match self.pty.close_stream(token) {
+            Some((session_id, attached)) => {
+                if let Some(conn_id) = attached {
+                    self.connections.detach_pty(&conn_id, &session_id);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the workspace shown for sessions whose tasks name none.
+    pub fn set_default_workspace(&mut self, workspace: impl Into<String>) {
+        self.default_workspace = Some(workspace.into());
+    }
+
+    /// Workspace tasks run in when they name none.
+    pub fn default_workspace(&self) -> Option<&str> {
+        self.default_workspace.as_deref()
+    }
+
+    /// Report the focus block of `workspace` in `/api/status`, so the
+    /// dashboard and channel bots can tell that the user is focusing.
+    pub fn set_focus_workspace(&mut self, workspace: impl Into<PathBuf>) {
+        self.focus_workspace = Some(workspace.into());
+    }
+
+    /// The open focus block of the focus workspace, if any.
+    pub fn focus_status(&self) -> Option<serde_json::Value> {
+        let workspace = self.focus_workspace.as_deref()?;
+        let state = crate::focus::FocusState::load(workspace);
+        let block = state.active_at(Utc::now())?;
+        Some(serde_json::json!({
+            "task": block.task,
+            "started_at": block.started_at,
+            "ends_at": block.ends_at(),
+            "status": block.status_line(),
+            "held": block.held.len(),
+            "interruptions": block.interruptions.len(),
+        }))
+    }
+
+    /// Serve `/api/stats/cost` from the event store at `path`, and report
+    /// `cap` in `/api/status`.
+    pub fn set_cost_tracking(
+        &mut self,
+        path: impl Into<PathBuf>,
+        cap: Option<crate::event_store::CostCap>,
+    ) {
+        self.event_store_path = Some(path.into());
+        self.cost_cap = cap;
+    }
+
+    /// This month's spend against the soft cap, if one is set.
+    pub fn cost_cap_status(&self) -> Option<serde_json::Value> {
+        let status = match self.cost_cap.as_ref()?.status(Utc::now()) {
+            Ok(status) => status,
+            Err(e) => {
+                tracing::warn!("Monthly cost unavailable: {}", e);
+                return None;
+            }
+        };
+        Some(serde_json::json!({
+            "month": status.month,
+            "spent_usd": status.spent_usd,
+            "cap_usd": status.cap_usd,
+            "unpriced_requests": status.unpriced_requests,
+            "exceeded": status.exceeded(),
+            "warning": status.exceeded().then(|| status.warning()),
+        }))
+    }
+
+    /// Serve cron job history from the scheduler state directory `dir`.
+    pub fn set_cron_state_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.cron_state_dir = Some(dir.into());
+    }
+
+    /// Set the configuration the gateway was started from. Reloads compare
+    /// against it to decide what changed.
+    pub fn set_agent_config(&mut self, config: &AgentConfig) {
+        self.reload.set_baseline(config);
+    }
+
+    /// Apply `[safety.approval_timeouts]` to approvals requested by tasks
+    /// running in `mode`.
+    pub fn set_approval_timeouts(&mut self, mode: ApprovalMode, timeouts: ApprovalTimeoutConfig) {
+        self.approval_mode = mode;
+        self.approval_timeouts = timeouts;
+    }
+
+    /// Set what delivers escalated approvals.
+    pub fn set_approval_escalator(&mut self, escalator: Arc<dyn ApprovalEscalator>) {
+        self.approval_escalator = Some(escalator);
+    }
+
+    /// The timeout policy for an approval at `risk`, if any.
+    pub fn approval_policy(&self, risk: RiskLevel) -> Option<ResolvedPolicy> {
+        self.approval_timeouts.resolve(self.approval_mode, risk)
+    }
+
+    pub(crate) fn approval_escalator(&self) -> Option<Arc<dyn ApprovalEscalator>> {
+        self.approval_escalator.clone()
+    }
+
+    /// Set where [`reload_from_source`](Self::reload_from_source) reads configuration.
+    pub fn set_config_loader(&mut self, loader: ConfigLoader) {
+        self.reload.set_loader(loader);
+    }
+
+    /// Register a handler that applies one config section on reload.
+    pub fn add_reload_handler(&mut self, handler: Box<dyn ReloadHandler>) {
+        self.reload.add_handler(handler);
+    }
+
+    /// Apply the live-reloadable settings in `config` and report the rest as
+    /// deferred. WebSocket connections stay open throughout; a new
+    /// `event_queue_capacity` applies to connections opened afterwards.
+    ///
+    /// Call [`dispatch_tasks`] afterwards in case task slots were added.
+    pub fn reload(&mut self, config: &AgentConfig) -> ReloadReport {
+        let report = self.reload.apply(config, &mut self.config);
+        let changed = |name: &str| report.applied.iter().any(|s| s == name);
+        if changed("gateway.auth_tokens") || changed("gateway.task_tokens") {
+            self.auth = GatewayAuth::from_config(&self.config);
+        }
+        if changed("gateway.max_connections") {
+            self.connections
+                .set_max_connections(self.config.max_connections);
+        }
+        if changed("gateway.max_concurrent_tasks") || changed("gateway.max_queued_tasks") {
+            self.tasks.set_limits(
+                self.config.max_concurrent_tasks,
+                self.config.max_queued_tasks,
+            );
+        }
+        self.finish_reload(report)
+    }
+
+    /// Load configuration with the attached loader and [`reload`](Self::reload) it.
+    pub fn reload_from_source(&mut self) -> ReloadReport {
+        match self.reload.load() {
+            Ok(config) => self.reload(&config),
+            Err(e) => self.finish_reload(ReloadReport::failed(e)),
+        }
+    }
+
+    fn finish_reload(&mut self, report: ReloadReport) -> ReloadReport {
+        if report.errors.is_empty() {
+            tracing::info!("Gateway configuration reloaded: {}", report.summary());
+        } else {
+            tracing::warn!("Gateway configuration reload: {}", report.summary());
+        }
+        self.reload.record(report.clone());
+        self.broadcast(GatewayEvent::ConfigReloaded {
+            report: report.clone(),
+        });
+        report
+    }
+
+    /// The result of the most recent reload.
+    pub fn last_reload(&self) -> Option<&ReloadReport> {
+        self.reload.last()
+    }
+
+    /// Watch the shutdown phase.
+    pub fn shutdown_phase(&self) -> watch::Receiver<ShutdownPhase> {
+        self.shutdown.subscribe()
+    }
+
+    /// Whether [`begin_shutdown`](Self::begin_shutdown) has been called.
+    pub fn is_shutting_down(&self) -> bool {
+        *self.shutdown.borrow() != ShutdownPhase::Running
+    }
+
+    /// Stop accepting tasks, cancel queued ones, and tell clients the gateway
+    /// is going away in `grace`. Running tasks are left to finish; see [`shutdown`].
+    pub fn begin_shutdown(&mut self, grace: Duration) {
+        if self.is_shutting_down() {
+            return;
+        }
+        let grace_secs = grace.as_secs();
+        tracing::info!(grace_secs, "Gateway shutting down");
+        self.shutdown
+            .send_replace(ShutdownPhase::Draining { grace_secs });
+        for task_id in self.tasks.drain_queued() {
+            self.broadcast(GatewayEvent::TaskUpdate {
+                task_id,
+                update: TaskUpdate::Cancelled,
+            });
+            self.broadcast(GatewayEvent::TaskCompleted {
+                task_id,
+                success: false,
+                summary: "Gateway shut down before the task started".to_string(),
+            });
+        }
+    }
+
     /// Set the shared toggle state for voice/meeting controls.
+    ///
+    /// Actions denied by voice are forwarded as
+    /// [`GatewayEvent::VoiceApprovalDenied`] so the dashboard and paired
+    /// devices see them.
     pub fn set_toggle_state(&mut self, state: Arc<crate::voice::toggle::ToggleState>) {
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let mut denials = state.subscribe_voice_denials();
+            let events = self.event_tx.clone();
+            runtime.spawn(async move {
+                loop {
+                    match denials.recv().await {
+                        Ok(denial) => {
+                            let _ = events.send(GatewayEvent::VoiceApprovalDenied {
+                                tool_name: denial.tool_name,
+                                description: denial.description,
+                                heard: denial.heard,
+                            });
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
         self.toggle_state = Some(state);
     }
 
@@ -173,6 +763,19 @@ impl GatewayServer {
         self.sessions.active_count()
     }
 
+    /// Workspaces of active sessions with their session counts, by name.
+    pub fn active_workspaces(&self) -> Vec<(String, usize)> {
+        let mut counts = std::collections::BTreeMap::new();
+        for session in self.sessions.list_active() {
+            let workspace = session
+                .workspace
+                .clone()
+                .unwrap_or_else(|| "default".to_string());
+            *counts.entry(workspace).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
+    }
+
     /// Increment the tool call counter.
     pub fn record_tool_call(&mut self) {
         self.total_tool_calls += 1;
@@ -199,23 +802,233 @@ impl GatewayServer {
         let tool_name = approval.tool_name.clone();
         let description = approval.description.clone();
         let risk_level = approval.risk_level.clone();
+        let expires_at = approval.expires_at;
         self.pending_approvals.insert(id, approval);
         self.broadcast(GatewayEvent::ApprovalRequest {
             approval_id: id,
             tool_name,
             description,
             risk_level,
+            expires_at,
         });
     }
 
+    /// Add a pending approval and return a receiver for the decision.
+    pub fn add_approval_waiter(&mut self, approval: PendingApproval) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.approval_waiters.insert(approval.id, tx);
+        self.add_approval(approval);
+        rx
+    }
+
     /// Resolve a pending approval (returns true if found). O(1) via HashMap.
-    pub fn resolve_approval(&mut self, approval_id: &Uuid, _approved: bool) -> bool {
+    pub fn resolve_approval(&mut self, approval_id: &Uuid, approved: bool) -> bool {
+        if let Some(waiter) = self.approval_waiters.remove(approval_id) {
+            let _ = waiter.send(approved);
+        }
         self.pending_approvals.remove(approval_id).is_some()
     }
 
-    /// Get all pending approvals.
+    /// Get all pending approvals, oldest first.
     pub fn pending_approvals(&self) -> Vec<&PendingApproval> {
-        self.pending_approvals.values().collect()
+        let mut approvals: Vec<_> = self.pending_approvals.values().collect();
+        approvals.sort_by_key(|a| a.created_at);
+        approvals
+    }
+
+    /// Mark a still-pending approval as escalated to `channel`, now open
+    /// until `expires_at`. Returns false if it was decided meanwhile.
+    pub fn escalate_approval(
+        &mut self,
+        approval_id: &Uuid,
+        channel: &str,
+        expires_at: DateTime<Utc>,
+    ) -> bool {
+        let Some(approval) = self.pending_approvals.get_mut(approval_id) else {
+            return false;
+        };
+        approval.escalated = true;
+        approval.expires_at = Some(expires_at);
+        self.broadcast(GatewayEvent::ApprovalEscalated {
+            approval_id: *approval_id,
+            channel: channel.to_string(),
+            expires_at,
+        });
+        true
+    }
+
+    /// Deny a still-pending approval whose windows have all passed, audit
+    /// it under `policy` and notify clients. Returns `None` if a decision
+    /// arrived first; that decision stands.
+    pub fn expire_approval(
+        &mut self,
+        approval_id: &Uuid,
+        policy: &ResolvedPolicy,
+    ) -> Option<ExpiredApproval> {
+        let approval = self.pending_approvals.remove(approval_id)?;
+        self.approval_waiters.remove(approval_id);
+        let expired = ExpiredApproval {
+            approval_id: approval.id,
+            tool_name: approval.tool_name,
+            description: approval.description,
+            risk_level: approval.risk_level,
+            created_at: approval.created_at,
+            expired_at: Utc::now(),
+            policy: policy.source.clone(),
+            escalated: approval.escalated,
+            resolution: policy.fallback,
+        };
+        self.record_audit(AuditEvent::ApprovalExpired {
+            tool: expired.tool_name.clone(),
+            policy: expired.policy.clone(),
+            resolution: expired.resolution.to_string(),
+            escalated: expired.escalated,
+        });
+        self.broadcast(GatewayEvent::ApprovalExpired {
+            approval_id: expired.approval_id,
+            tool_name: expired.tool_name.clone(),
+            policy: expired.policy.clone(),
+            resolution: expired.resolution.to_string(),
+        });
+        Some(expired)
+    }
+
+    /// Show a form on connected clients and accept submissions for it.
+    /// Without a waiting task, a submission starts the form's `on_submit`
+    /// task, if it has one.
+    pub fn add_form(&mut self, form: PendingForm) {
+        self.register_form(form, None);
+    }
+
+    /// Show a form and return a receiver for its first valid submission,
+    /// or [`FormOutcome::Expired`] once [`expire_form`](Self::expire_form)
+    /// closes it.
+    pub fn add_form_waiter(&mut self, form: PendingForm) -> oneshot::Receiver<FormOutcome> {
+        let (tx, rx) = oneshot::channel();
+        self.register_form(form, Some(tx));
+        rx
+    }
+
+    fn register_form(&mut self, form: PendingForm, waiter: Option<oneshot::Sender<FormOutcome>>) {
+        let spec = form.spec.public();
+        self.broadcast(GatewayEvent::FormRequest {
+            form_id: form.id,
+            html: render_form_html_for(&spec, form.id),
+            spec,
+            task_id: form.task_id,
+            expires_at: form.expires_at,
+        });
+        self.forms.add(form, waiter);
+    }
+
+    /// Submit values for a pending form on behalf of `connection_id`
+    /// (`None` for REST). The first valid submission wins: it reaches the
+    /// waiting task or starts the `on_submit` task, is audited with
+    /// sensitive fields redacted, and closes the form on every client.
+    ///
+    /// Call [`dispatch_tasks`] afterwards if a task was started.
+    pub fn submit_form(
+        &mut self,
+        form_id: &Uuid,
+        values: &serde_json::Map<String, serde_json::Value>,
+        connection_id: Option<Uuid>,
+    ) -> Result<FormReceipt, FormSubmitError> {
+        self.expire_due_forms();
+        let accepted = self.forms.submit(form_id, values, connection_id)?;
+        let spec = &accepted.form.spec;
+        let redacted = redact_submission(spec, &accepted.values);
+        let client = connection_id
+            .and_then(|id| self.connections.get(&id))
+            .map_or_else(|| "REST client".to_string(), |c| c.label());
+        self.record_audit(AuditEvent::FormSubmitted {
+            form_id: *form_id,
+            title: spec.title.clone().unwrap_or_else(|| "untitled".to_string()),
+            client,
+            values: serde_json::Value::Object(redacted.clone()).to_string(),
+        });
+        self.broadcast(GatewayEvent::FormResolved {
+            form_id: *form_id,
+            resolution: FormResolution::Submitted,
+            connection_id,
+            values: redacted.clone(),
+        });
+
+        let mut task_id = None;
+        if !accepted.delivered
+            && let Some(template) = &spec.on_submit
+        {
+            let text = bind_template(template, spec, &accepted.values);
+            match self.submit_task(
+                text,
+                None,
+                TaskOptions::default(),
+                connection_id.unwrap_or_default(),
+            ) {
+                Ok((id, _)) => task_id = Some(id),
+                Err(e) => tracing::warn!("Form {} submitted but no task started: {}", form_id, e),
+            }
+        }
+        Ok(FormReceipt {
+            values: redacted,
+            task_id,
+        })
+    }
+
+    /// Close a pending form as expired: its waiting task resolves with a
+    /// timeout and clients close it. Returns false if it was submitted
+    /// first; that submission stands.
+    pub fn expire_form(&mut self, form_id: &Uuid) -> bool {
+        match self.forms.expire(form_id) {
+            Some(closed) => {
+                self.announce_expired_form(&closed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Expire every form whose submission window has passed.
+    fn expire_due_forms(&mut self) {
+        for closed in self.forms.expire_due(Utc::now()) {
+            self.announce_expired_form(&closed);
+        }
+    }
+
+    fn announce_expired_form(&self, closed: &ClosedForm) {
+        self.broadcast(GatewayEvent::FormResolved {
+            form_id: closed.id,
+            resolution: FormResolution::Expired,
+            connection_id: None,
+            values: serde_json::Map::new(),
+        });
+    }
+
+    /// Forms accepting submissions, oldest first.
+    pub fn pending_forms(&mut self) -> Vec<&PendingForm> {
+        self.expire_due_forms();
+        self.forms.pending()
+    }
+
+    /// Recently submitted or expired forms, oldest first, with sensitive
+    /// values redacted.
+    pub fn closed_forms(&self) -> &VecDeque<ClosedForm> {
+        self.forms.closed()
+    }
+
+    /// Register a session artifact and notify connected clients.
+    pub fn record_artifact(&mut self, artifact: ArtifactRecord) {
+        self.broadcast(GatewayEvent::ArtifactCreated {
+            artifact: artifact.clone(),
+        });
+        self.artifacts.push(artifact);
+    }
+
+    /// All registered artifacts, with stale flags refreshed.
+    pub fn artifacts(&mut self) -> &[ArtifactRecord] {
+        for artifact in &mut self.artifacts {
+            artifact.refresh_stale();
+        }
+        &self.artifacts
     }
 
     /// Set the configuration JSON snapshot for the UI.
@@ -231,14 +1044,31 @@ impl GatewayServer {
     /// Handle a client message and produce a server response.
     pub fn handle_client_message(&mut self, msg: ClientMessage, conn_id: Uuid) -> ServerMessage {
         match msg {
-            ClientMessage::Authenticate { token } => {
-                if self.auth.validate(&token) {
-                    self.connections.authenticate(&conn_id);
+            ClientMessage::Authenticate {
+                token,
+                client_name,
+                client_type,
+                ..
+            } => {
+                let token_id = if let Some((device_id, scope)) = self.devices.authenticate(&token) {
+                    self.connections
+                        .authenticate_device(&conn_id, scope, device_id);
+                    Some(format!("device:{}", device_id))
+                } else if let Some(scope) = self.auth.scope(&token) {
+                    self.connections.authenticate_with_scope(&conn_id, scope);
+                    Some(self.auth.token_id(&token))
+                } else {
+                    None
+                };
+                if let Some(token_id) = token_id {
+                    self.connections
+                        .identify(&conn_id, client_name, client_type, token_id);
                     self.broadcast(GatewayEvent::Connected {
                         connection_id: conn_id,
                     });
                     ServerMessage::Authenticated {
                         connection_id: conn_id,
+                        protocol_version: PROTOCOL_VERSION.to_string(),
                     }
                 } else {
                     ServerMessage::AuthFailed {
@@ -246,48 +1076,49 @@ impl GatewayServer {
                     }
                 }
             }
-            ClientMessage::SubmitTask { description } => {
-                if !self.connections.is_authenticated(&conn_id) {
-                    return ServerMessage::AuthFailed {
-                        reason: "Not authenticated".to_string(),
-                    };
+            ClientMessage::SubmitTask {
+                text,
+                workspace,
+                options,
+            } => {
+                if let Some(denied) = self.require_scope(&conn_id, AuthScope::Tasks) {
+                    return denied;
                 }
-                let task_id = Uuid::new_v4();
-                let _session_id = self.sessions.create_session(conn_id);
-                self.broadcast(GatewayEvent::TaskSubmitted {
-                    task_id,
-                    description: description.clone(),
-                });
-                ServerMessage::Event {
-                    event: GatewayEvent::TaskSubmitted {
-                        task_id,
-                        description,
+                let session_workspace =
+                    workspace.clone().or_else(|| self.default_workspace.clone());
+                match self.submit_task(text.clone(), workspace, options, conn_id) {
+                    Ok((task_id, _)) => {
+                        let _session_id =
+                            self.sessions.create_session_in(conn_id, session_workspace);
+                        ServerMessage::Event {
+                            event: GatewayEvent::TaskSubmitted {
+                                task_id,
+                                description: text,
+                            },
+                        }
+                    }
+                    Err(message) => ServerMessage::Event {
+                        event: GatewayEvent::Error {
+                            code: "TASK_REJECTED".to_string(),
+                            message,
+                        },
                     },
                 }
             }
             ClientMessage::CancelTask { task_id } => {
-                if !self.connections.is_authenticated(&conn_id) {
-                    return ServerMessage::AuthFailed {
-                        reason: "Not authenticated".to_string(),
-                    };
+                if let Some(denied) = self.require_scope(&conn_id, AuthScope::Tasks) {
+                    return denied;
                 }
-                self.broadcast(GatewayEvent::TaskCompleted {
+                ServerMessage::CancelAck {
                     task_id,
-                    success: false,
-                    summary: "Cancelled by client".to_string(),
-                });
-                ServerMessage::Event {
-                    event: GatewayEvent::TaskCompleted {
-                        task_id,
-                        success: false,
-                        summary: "Cancelled by client".to_string(),
-                    },
+                    accepted: self.cancel_task(&task_id),
                 }
             }
             ClientMessage::GetStatus => ServerMessage::StatusResponse {
                 connected_clients: self.connections.active_count(),
-                active_tasks: self.sessions.active_count(),
+                active_tasks: self.tasks.running_count() + self.tasks.queued_count(),
                 uptime_secs: self.uptime_secs(),
+                protocol_version: PROTOCOL_VERSION.to_string(),
             },
             ClientMessage::Ping { timestamp } => ServerMessage::Pong { timestamp },
             ClientMessage::ListChannels => {
@@ -312,49 +1143,320 @@ impl GatewayServer {
                 total_tool_calls: self.total_tool_calls,
                 total_llm_requests: self.total_llm_requests,
                 uptime_secs: self.uptime_secs(),
+                llm_providers: crate::providers::limiter_stats(),
             },
-            ClientMessage::GetConfig => ServerMessage::ConfigResponse {
-                config_json: self.config_json.clone(),
-            },
+            ClientMessage::GetConfig => {
+                if let Some(denied) = self.require_scope(&conn_id, AuthScope::Read) {
+                    return denied;
+                }
+                ServerMessage::ConfigResponse {
+                    config_json: self.config_json.clone(),
+                }
+            }
             ClientMessage::ApprovalDecision {
                 approval_id,
                 approved,
                 reason: _,
             } => {
+                if let Some(denied) = self.require_scope(&conn_id, AuthScope::Read) {
+                    return denied;
+                }
                 let found = self.resolve_approval(&approval_id, approved);
                 ServerMessage::ApprovalAck {
                     approval_id,
                     accepted: found,
                 }
             }
+            ClientMessage::Subscribe { topics } => ServerMessage::Subscriptions {
+                topics: self
+                    .connections
+                    .subscribe(&conn_id, &topics)
+                    .unwrap_or_default(),
+            },
+            ClientMessage::Unsubscribe { topics } => ServerMessage::Subscriptions {
+                topics: self
+                    .connections
+                    .unsubscribe(&conn_id, &topics)
+                    .unwrap_or_default(),
+            },
+            ClientMessage::PtyOpenStream { session_id } => {
+                if let Some(denied) = self.require_scope(&conn_id, AuthScope::Read) {
+                    return denied;
+                }
+                let scope = self
+                    .connections
+                    .get(&conn_id)
+                    .map_or(AuthScope::Read, |c| c.scope);
+                match self.pty.open_stream(session_id, scope) {
+                    Ok(stream) => ServerMessage::PtyStream { stream },
+                    Err(e) => pty_error(e),
+                }
+            }
+            ClientMessage::PtyAttach { token } => {
+                if let Some(denied) = self.require_scope(&conn_id, AuthScope::Read) {
+                    return denied;
+                }
+                let (session_id, token_scope) = match self.pty.redeem(&token, conn_id) {
+                    Ok(grant) => grant,
+                    Err(e) => return pty_error(e),
+                };
+                let scope = self
+                    .connections
+                    .get(&conn_id)
+                    .map_or(AuthScope::Read, |c| c.scope.min(token_scope));
+                match self.pty.scrollback(&session_id) {
+                    Ok(scrollback) => {
+                        self.connections.attach_pty(&conn_id, session_id, scope);
+                        ServerMessage::PtyAttached {
+                            session_id,
+                            scrollback,
+                            writable: scope >= AuthScope::Tasks,
+                        }
+                    }
+                    Err(e) => pty_error(e),
+                }
+            }
+            ClientMessage::PtyInput { session_id, data } => {
+                if let Some(denied) = self.require_pty_writer(&conn_id, &session_id) {
+                    return denied;
+                }
+                ServerMessage::PtyAck {
+                    session_id,
+                    accepted: self.pty.write(&session_id, &data).is_ok(),
+                }
+            }
+            ClientMessage::PtyResize {
+                session_id,
+                cols,
+                rows,
+            } => {
+                if let Some(denied) = self.require_pty_writer(&conn_id, &session_id) {
+                    return denied;
+                }
+                ServerMessage::PtyAck {
+                    session_id,
+                    accepted: self.pty.resize(&session_id, cols, rows).is_ok(),
+                }
+            }
+            ClientMessage::PtyDetach { session_id } => ServerMessage::PtyAck {
+                session_id,
+                accepted: self.connections.detach_pty(&conn_id, &session_id),
+            },
+            ClientMessage::PtyKill { session_id } => {
+                if let Some(denied) = self.require_pty_writer(&conn_id, &session_id) {
+                    return denied;
+                }
+                ServerMessage::PtyAck {
+                    session_id,
+                    accepted: self.pty.kill(&session_id).is_ok(),
+                }
+            }
+            ClientMessage::CreatePairing { scope } => {
+                if let Some(denied) = self.require_operator(&conn_id) {
+                    return denied;
+                }
+                let offer = self.create_pairing_offer(scope);
+                match qr_png(&offer.url, 6) {
+                    Ok(png) => ServerMessage::PairingOffer {
+                        offer,
+                        qr_png: base64::engine::general_purpose::STANDARD.encode(png),
+                    },
+                    Err(e) => pairing_error(e),
+                }
+            }
+            ClientMessage::ListDevices => {
+                if let Some(denied) = self.require_operator(&conn_id) {
+                    return denied;
+                }
+                ServerMessage::Devices {
+                    devices: self.paired_devices(),
+                }
+            }
+            ClientMessage::RevokeDevice { device_id } => {
+                if let Some(denied) = self.require_operator(&conn_id) {
+                    return denied;
+                }
+                match self.revoke_device(&device_id) {
+                    Ok(_) => ServerMessage::Devices {
+                        devices: self.paired_devices(),
+                    },
+                    Err(e) => pairing_error(e),
+                }
+            }
+            ClientMessage::SubmitForm { form_id, values } => {
+                if let Some(denied) = self.require_scope(&conn_id, AuthScope::Read) {
+                    return denied;
+                }
+                match self.submit_form(&form_id, &values, Some(conn_id)) {
+                    Ok(receipt) => ServerMessage::FormAck {
+                        form_id,
+                        accepted: true,
+                        error: None,
+                        errors: Vec::new(),
+                        task_id: receipt.task_id,
+                    },
+                    Err(e) => ServerMessage::FormAck {
+                        form_id,
+                        accepted: false,
+                        error: Some(e.to_string()),
+                        errors: match e {
+                            FormSubmitError::Invalid(errors) => errors,
+                            _ => Vec::new(),
+                        },
+                        task_id: None,
+                    },
+                }
+            }
         }
     }
-}
 
-/// Build an axum Router with `/ws`, `/health`, and REST API routes.
-pub fn router(shared: SharedGateway) -> Router {
-    Router::new()
-        .route("/ws", get(ws_handler))
-        .route("/health", get(health_handler))
-        .route("/api/status", get(api_status_handler))
+    /// Reject a PTY message unless the connection is attached to the session
+    /// with the `tasks` scope.
+    fn require_pty_writer(&self, conn_id: &Uuid, session_id: &Uuid) -> Option<ServerMessage> {
+        if !self.connections.is_authenticated(conn_id) {
+            return Some(ServerMessage::AuthFailed {
+                reason: "Not authenticated".to_string(),
+            });
+        }
+        match self.connections.pty_scope(conn_id, session_id) {
+            Some(AuthScope::Tasks) => None,
+            Some(AuthScope::Status | AuthScope::Read) => Some(forbidden(
+                "This terminal stream is read-only; typing requires a task token",
+            )),
+            None => Some(forbidden("Not attached to this terminal session")),
+        }
+    }
+
+    /// Reject a message unless the connection holds `scope`.
+    fn require_scope(&self, conn_id: &Uuid, scope: AuthScope) -> Option<ServerMessage> {
+        if !self.connections.is_authenticated(conn_id) {
+            return Some(ServerMessage::AuthFailed {
+                reason: "Not authenticated".to_string(),
+            });
+        }
+        if !self.connections.has_scope(conn_id, scope) {
+            return Some(forbidden(&format!(
+                "This token lacks the '{}' scope required for this request",
+                scope
+            )));
+        }
+        None
+    }
+
+    /// Reject a message unless it comes from an operator: a configured token
+    /// with the `tasks` scope, not a paired device.
+    fn require_operator(&self, conn_id: &Uuid) -> Option<ServerMessage> {
+        if let Some(denied) = self.require_scope(conn_id, AuthScope::Tasks) {
+            return Some(denied);
+        }
+        if self
+            .connections
+            .get(conn_id)
+            .is_some_and(|c| c.device_id.is_some())
+        {
+            return Some(forbidden("Paired devices cannot manage pairing"));
+        }
+        None
+    }
+}
+
+fn append_audit(path: &std::path::Path, entry: &GatewayAuditEntry) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let line = serde_json::to_string(entry)?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line)
+}
+
+fn forbidden(message: &str) -> ServerMessage {
+    ServerMessage::Event {
+        event: GatewayEvent::Error {
+            code: "FORBIDDEN".to_string(),
+            message: message.to_string(),
+        },
+    }
+}
+
+fn pairing_error(error: PairingError) -> ServerMessage {
+    ServerMessage::Event {
+        event: GatewayEvent::Error {
+            code: "PAIRING_ERROR".to_string(),
+            message: error.to_string(),
+        },
+    }
+}
+
+fn pty_error(error: PtyError) -> ServerMessage {
+    ServerMessage::Event {
+        event: GatewayEvent::Error {
+            code: "PTY_ERROR".to_string(),
+            message: error.to_string(),
+        },
+    }
+}
+
+/// Build an axum Router with `/ws`, `/health`, and REST API routes.
+pub fn router(shared: SharedGateway) -> Router {
+    Router::new()
+        .route("/ws", get(ws_handler))
+        .route("/health", get(health_handler))
+        .route("/api/status", get(api_status_handler))
         .route("/api/sessions", get(api_sessions_handler))
         .route("/api/config", get(api_config_handler))
         .route("/api/metrics", get(api_metrics_handler))
+        .route("/api/stats/cost", get(api_cost_handler))
         .route("/api/audit", get(api_audit_handler))
+        .route("/api/cron/{name}/history", get(api_cron_history_handler))
         .route("/api/approvals", get(api_approvals_handler))
         .route("/api/approval/{id}", post(api_approval_decision_handler))
+        .route(
+            "/api/forms",
+            get(api_forms_handler).post(api_form_create_handler),
+        )
+        .route("/api/forms/{id}/submit", post(api_form_submit_handler))
+        .route("/api/artifacts", get(api_artifacts_handler))
+        .route("/api/artifacts/{id}/open", post(api_artifact_open_handler))
+        .route("/api/attachments/{id}", get(api_attachment_handler))
+        .route("/api/pty", get(api_pty_handler))
+        .route("/api/reload", post(api_reload_handler))
+        .route("/pair", get(pair_page_handler))
+        .route("/api/pair", post(api_pair_handler))
+        .route("/api/pairing", post(api_pairing_handler))
+        .route("/api/devices", get(api_devices_handler))
+        .route("/api/devices/{id}", delete(api_device_revoke_handler))
+        .route("/api/clients", get(api_clients_handler))
+        .route("/api/clients/{id}", delete(api_client_disconnect_handler))
         .route("/api/voice/start", post(api_voice_start_handler))
         .route("/api/voice/stop", post(api_voice_stop_handler))
         .route("/api/voice/status", get(api_voice_status_handler))
         .route("/api/meeting/start", post(api_meeting_start_handler))
         .route("/api/meeting/stop", post(api_meeting_stop_handler))
         .route("/api/meeting/status", get(api_meeting_status_handler))
+        .layer(middleware::map_response(protocol_header))
         .with_state(shared)
 }
 
-/// WebSocket upgrade handler.
-async fn ws_handler(ws: WebSocketUpgrade, State(gw): State<SharedGateway>) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, gw))
+/// Stamp every response with the protocol version.
+async fn protocol_header(mut response: Response) -> Response {
+    response.headers_mut().insert(
+        HeaderName::from_static(PROTOCOL_HEADER),
+        HeaderValue::from_static(PROTOCOL_VERSION),
+    );
+    response
+}
+
+/// WebSocket upgrade handler. The peer address is known when the router is
+/// served with `into_make_service_with_connect_info::<SocketAddr>()`.
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(gw): State<SharedGateway>,
+    peer: Option<Extension<ConnectInfo<SocketAddr>>>,
+) -> impl IntoResponse {
+    let remote_addr = peer.map(|Extension(ConnectInfo(addr))| addr);
+    ws.on_upgrade(move |socket| handle_socket(socket, gw, remote_addr))
 }
 
 /// Health check endpoint.
@@ -385,18 +1487,39 @@ async fn api_status_handler(State(gw): State<SharedGateway>) -> impl IntoRespons
 
     let body = serde_json::json!({
         "version": env!("CARGO_PKG_VERSION"),
+        "protocol_version": PROTOCOL_VERSION,
         "uptime_secs": gw.uptime_secs(),
         "active_connections": gw.active_connections(),
+        "disconnects": gw.connections().disconnect_counts(),
         "active_sessions": gw.active_sessions(),
         "total_tool_calls": gw.total_tool_calls(),
         "total_llm_requests": gw.total_llm_requests(),
         "channels": channels.iter().map(|(n, s)| serde_json::json!({"name": n, "status": s})).collect::<Vec<_>>(),
         "nodes": nodes.iter().map(|(n, s)| serde_json::json!({"name": n, "status": s})).collect::<Vec<_>>(),
         "pending_approvals": gw.pending_approvals().len(),
+        "default_workspace": gw.default_workspace(),
+        "workspaces": gw.active_workspaces().iter().map(|(n, c)| serde_json::json!({"name": n, "sessions": c})).collect::<Vec<_>>(),
+        "shutting_down": gw.is_shutting_down(),
+        "last_reload": gw.last_reload(),
+        "focus": gw.focus_status(),
+        "cost_cap": gw.cost_cap_status(),
     });
     axum::Json(body)
 }
 
+/// REST API: Re-read configuration and apply what can change live.
+async fn api_reload_handler(State(gw): State<SharedGateway>) -> impl IntoResponse {
+    let report = gw.lock().await.reload_from_source();
+    // New task slots may have opened.
+    dispatch_tasks(gw.clone()).await;
+    let status = if report.errors.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+    (status, axum::Json(report))
+}
+
 /// REST API: Get active sessions.
 async fn api_sessions_handler(State(gw): State<SharedGateway>) -> impl IntoResponse {
     let gw = gw.lock().await;
@@ -407,6 +1530,7 @@ async fn api_sessions_handler(State(gw): State<SharedGateway>) -> impl IntoRespo
                 "id": s.session_id.to_string(),
                 "connection_id": s.connection_id.to_string(),
                 "state": format!("{:?}", s.state),
+                "workspace": s.workspace,
                 "created_at": s.created_at.to_rfc3339(),
             })
         }).collect::<Vec<_>>(),
@@ -429,25 +1553,360 @@ async fn api_metrics_handler(State(gw): State<SharedGateway>) -> impl IntoRespon
     let gw = gw.lock().await;
     let body = serde_json::json!({
         "active_connections": gw.active_connections(),
+        "disconnects": gw.connections().disconnect_counts(),
         "active_sessions": gw.active_sessions(),
         "total_tool_calls": gw.total_tool_calls(),
         "total_llm_requests": gw.total_llm_requests(),
         "uptime_secs": gw.uptime_secs(),
+        "llm_providers": crate::providers::limiter_stats(),
     });
     axum::Json(body)
 }
 
+/// Query of `/api/stats/cost`.
+#[derive(Debug, Deserialize)]
+struct CostQuery {
+    /// `YYYY-MM`; defaults to the current month.
+    month: Option<String>,
+    /// Chart only this workspace's spend.
+    workspace: Option<String>,
+}
+
+/// REST API: LLM cost of a month per workspace and model, with a chart of
+/// the cumulative spend. The chart marks the soft cap when it covers the
+/// capped workspace.
+async fn api_cost_handler(
+    Query(query): Query<CostQuery>,
+    State(gw): State<SharedGateway>,
+) -> impl IntoResponse {
+    let (path, cap, capped_workspace) = {
+        let gw = gw.lock().await;
+        (
+            gw.event_store_path.clone(),
+            gw.cost_cap_status(),
+            gw.cost_cap.as_ref().map(|cap| cap.workspace().to_string()),
+        )
+    };
+    let error = |status: StatusCode, message: String| {
+        (status, axum::Json(serde_json::json!({"error": message})))
+    };
+    let Some(path) = path else {
+        return error(
+            StatusCode::NOT_FOUND,
+            "The event store is not enabled".into(),
+        );
+    };
+    let month = query
+        .month
+        .unwrap_or_else(|| crate::event_store::month_of(Utc::now()));
+    if !crate::event_store::is_month(&month) {
+        return error(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid month '{}'; expected YYYY-MM", month),
+        );
+    }
+    let workspace = query.workspace.clone();
+    let report = tokio::task::spawn_blocking(move || {
+        let store = crate::event_store::EventStore::open_read_only(&path)?;
+        Ok::<_, crate::event_store::EventStoreError>((
+            store.month_by_workspace(&month)?,
+            store.month_by_day(&month, workspace.as_deref())?,
+            month,
+        ))
+    })
+    .await;
+    let (rows, days, month) = match report {
+        Ok(Ok(report)) => report,
+        Ok(Err(crate::event_store::EventStoreError::Missing(_))) => {
+            return error(StatusCode::NOT_FOUND, "No events recorded yet".into());
+        }
+        Ok(Err(e)) => return error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+    let cap_usd = cap
+        .as_ref()
+        .filter(|_| query.workspace.is_some() && query.workspace == capped_workspace)
+        .and_then(|cap| cap.get("cap_usd"))
+        .and_then(|v| v.as_f64());
+    let chart = crate::event_store::cost_chart(&month, &days, cap_usd);
+    (
+        StatusCode::OK,
+        axum::Json(serde_json::json!({
+            "month": month,
+            "total_usd": rows.iter().map(|r| r.cost_usd).sum::<f64>(),
+            "unpriced_requests": rows.iter().map(|r| r.unpriced_requests).sum::<u64>(),
+            "rows": rows,
+            "chart": chart,
+            "cost_cap": cap,
+        })),
+    )
+}
+
+/// REST API: Recent runs of a cron job, newest first.
+async fn api_cron_history_handler(
+    Path(name): Path<String>,
+    State(gw): State<SharedGateway>,
+) -> impl IntoResponse {
+    let Some(dir) = gw.lock().await.cron_state_dir.clone() else {
+        return (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": "Cron history is not available"})),
+        );
+    };
+    let history = crate::scheduler::JobHistory::load(&dir, usize::MAX);
+    let runs = history.runs(&name);
+    (
+        StatusCode::OK,
+        axum::Json(serde_json::json!({
+            "job": name,
+            "total": runs.len(),
+            "runs": runs,
+        })),
+    )
+}
+
 /// REST API: Get audit trail (placeholder — returns recent events).
-async fn api_audit_handler(State(_gw): State<SharedGateway>) -> impl IntoResponse {
-    // In a full implementation, this would query the AuditTrail from rustant-core.
-    // For now, return an empty list to indicate the endpoint is functional.
+async fn api_audit_handler(State(gw): State<SharedGateway>) -> impl IntoResponse {
+    let gw = gw.lock().await;
+    let entries: Vec<serde_json::Value> = gw
+        .audit_entries()
+        .iter()
+        .map(|entry| {
+            let (action, details) = match &entry.event {
+                AuditEvent::DevicePairing {
+                    action,
+                    device,
+                    detail,
+                } => (
+                    format!("device {}", action),
+                    format!("{}: {}", device, detail),
+                ),
+                AuditEvent::ClientDisconnected { client, detail, .. } => (
+                    "client disconnected".to_string(),
+                    format!("{} {}", client, detail),
+                ),
+                AuditEvent::ApprovalExpired {
+                    tool,
+                    policy,
+                    resolution,
+                    ..
+                } => (
+                    format!("approval expired: {}", tool),
+                    format!("{} ({})", resolution, policy),
+                ),
+                AuditEvent::FormSubmitted {
+                    title,
+                    client,
+                    values,
+                    ..
+                } => (
+                    format!("form submitted: {}", title),
+                    format!("{} {}", client, values),
+                ),
+                other => ("event".to_string(), format!("{:?}", other)),
+            };
+            serde_json::json!({
+                "timestamp": entry.timestamp.to_rfc3339(),
+                "action": action,
+                "details": details,
+            })
+        })
+        .collect();
     let body = serde_json::json!({
-        "entries": [],
-        "total": 0,
+        "total": entries.len(),
+        "entries": entries,
     });
     axum::Json(body)
 }
 
+/// Whether a REST request carries an operator token: a configured token
+/// with the `tasks` scope. Device tokens never qualify; open mode accepts all.
+fn is_operator(gw: &GatewayServer, headers: &HeaderMap) -> bool {
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    gw.auth().scope(token) == Some(AuthScope::Tasks)
+}
+
+fn operator_required() -> (StatusCode, axum::Json<serde_json::Value>) {
+    (
+        StatusCode::FORBIDDEN,
+        axum::Json(serde_json::json!({"error": "A task token is required"})),
+    )
+}
+
+/// Page a device opens from the pairing QR code.
+async fn pair_page_handler() -> Html<&'static str> {
+    Html(include_str!("pair.html"))
+}
+
+/// REST API: Complete a pairing offer. Public; the HMAC over the offer's
+/// nonce proves the device scanned the QR code.
+async fn api_pair_handler(
+    State(gw): State<SharedGateway>,
+    axum::Json(request): axum::Json<DevicePairingRequest>,
+) -> impl IntoResponse {
+    match gw.lock().await.complete_pairing(&request) {
+        Ok((_, grant)) => (StatusCode::OK, axum::Json(serde_json::json!(grant))),
+        Err(e) => {
+            let status = match &e {
+                PairingError::UnknownChallenge => StatusCode::NOT_FOUND,
+                PairingError::Expired => StatusCode::GONE,
+                PairingError::Rejected => StatusCode::FORBIDDEN,
+                PairingError::InvalidDevice(_) => StatusCode::BAD_REQUEST,
+                PairingError::Qr(_) | PairingError::Store(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (
+                status,
+                axum::Json(serde_json::json!({"error": e.to_string()})),
+            )
+        }
+    }
+}
+
+/// REST API: Create a pairing offer with a QR code. Requires a task token.
+async fn api_pairing_handler(
+    State(gw): State<SharedGateway>,
+    headers: HeaderMap,
+    body: Option<axum::Json<serde_json::Value>>,
+) -> impl IntoResponse {
+    let mut gw = gw.lock().await;
+    if !is_operator(&gw, &headers) {
+        return operator_required();
+    }
+    let scope = match body
+        .as_ref()
+        .and_then(|b| b.get("scope"))
+        .and_then(|v| v.as_str())
+    {
+        Some(name) => match name.parse::<AuthScope>() {
+            Ok(scope) => scope,
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    axum::Json(serde_json::json!({"error": e})),
+                );
+            }
+        },
+        None => AuthScope::Status,
+    };
+    let offer = gw.create_pairing_offer(scope);
+    match qr_png(&offer.url, 6) {
+        Ok(png) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!({
+                "offer": offer,
+                "qr_png": base64::engine::general_purpose::STANDARD.encode(png),
+            })),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+/// REST API: List paired devices. Requires a task token.
+async fn api_devices_handler(
+    State(gw): State<SharedGateway>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let gw = gw.lock().await;
+    if !is_operator(&gw, &headers) {
+        return operator_required();
+    }
+    let devices = gw.paired_devices();
+    (
+        StatusCode::OK,
+        axum::Json(serde_json::json!({
+            "total": devices.len(),
+            "devices": devices,
+        })),
+    )
+}
+
+/// REST API: Revoke a paired device. Requires a task token.
+async fn api_device_revoke_handler(
+    Path(id): Path<String>,
+    State(gw): State<SharedGateway>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let mut gw = gw.lock().await;
+    if !is_operator(&gw, &headers) {
+        return operator_required();
+    }
+    let Ok(device_id) = Uuid::parse_str(&id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({"error": "Invalid UUID"})),
+        );
+    };
+    match gw.revoke_device(&device_id) {
+        Ok(Some(device)) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!({"revoked": device})),
+        ),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": "Device not found"})),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+/// REST API: List connected clients. Requires a task token.
+async fn api_clients_handler(
+    State(gw): State<SharedGateway>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let gw = gw.lock().await;
+    if !is_operator(&gw, &headers) {
+        return operator_required();
+    }
+    let clients = gw.connections().list();
+    (
+        StatusCode::OK,
+        axum::Json(serde_json::json!({
+            "total": clients.len(),
+            "clients": clients,
+            "disconnects": gw.connections().disconnect_counts(),
+        })),
+    )
+}
+
+/// REST API: Disconnect a client. Requires a task token.
+async fn api_client_disconnect_handler(
+    Path(id): Path<String>,
+    State(gw): State<SharedGateway>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let mut gw = gw.lock().await;
+    if !is_operator(&gw, &headers) {
+        return operator_required();
+    }
+    let Ok(connection_id) = Uuid::parse_str(&id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({"error": "Invalid UUID"})),
+        );
+    };
+    match gw.disconnect_client(&connection_id) {
+        Some(client) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!({"disconnected": client})),
+        ),
+        None => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": "Client not found"})),
+        ),
+    }
+}
+
 /// REST API: Get pending approval requests.
 async fn api_approvals_handler(State(gw): State<SharedGateway>) -> impl IntoResponse {
     let gw = gw.lock().await;
@@ -460,6 +1919,10 @@ async fn api_approvals_handler(State(gw): State<SharedGateway>) -> impl IntoResp
                 "tool_name": a.tool_name,
                 "description": a.description,
                 "risk_level": a.risk_level,
+                "created_at": a.created_at,
+                "expires_at": a.expires_at,
+                "escalated": a.escalated,
+                "policy": a.policy,
             })
         })
         .collect();
@@ -502,12 +1965,216 @@ async fn api_approval_decision_handler(
     }
 }
 
+/// REST API: List pending forms and recently closed ones.
+async fn api_forms_handler(State(gw): State<SharedGateway>) -> impl IntoResponse {
+    let mut gw = gw.lock().await;
+    let pending: Vec<serde_json::Value> = gw
+        .pending_forms()
+        .iter()
+        .map(|f| {
+            serde_json::json!({
+                "id": f.id,
+                "title": f.spec.title,
+                "task_id": f.task_id,
+                "created_at": f.created_at,
+                "expires_at": f.expires_at,
+                "spec": f.spec.public(),
+            })
+        })
+        .collect();
+    axum::Json(serde_json::json!({
+        "forms": pending,
+        "recent": gw.closed_forms(),
+    }))
+}
+
+/// REST API: Publish a form. Requires a task token.
+///
+/// Body: a `FormSpec`. With `on_submit`, the first valid submission starts
+/// that task.
+async fn api_form_create_handler(
+    State(gw): State<SharedGateway>,
+    headers: HeaderMap,
+    axum::Json(spec): axum::Json<FormSpec>,
+) -> impl IntoResponse {
+    let mut gw = gw.lock().await;
+    if !is_operator(&gw, &headers) {
+        return operator_required();
+    }
+    let problems = spec.problems();
+    if !problems.is_empty() {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            axum::Json(serde_json::json!({"error": problems.join("; ")})),
+        );
+    }
+    let form = PendingForm::new(spec, None);
+    let (id, expires_at) = (form.id, form.expires_at);
+    gw.add_form(form);
+    (
+        StatusCode::CREATED,
+        axum::Json(serde_json::json!({"id": id, "expires_at": expires_at})),
+    )
+}
+
+/// REST API: Submit values for a form.
+///
+/// Body: `{"values": {field: value}}`. Invalid values return `422` with
+/// per-field `errors`; a form already submitted by another client returns
+/// `409` and an expired one `410`.
+async fn api_form_submit_handler(
+    Path(id): Path<String>,
+    State(gw): State<SharedGateway>,
+    axum::Json(body): axum::Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let Ok(form_id) = Uuid::parse_str(&id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({"error": "Invalid UUID"})),
+        );
+    };
+    let Some(values) = body.get("values").and_then(|v| v.as_object()) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({"error": "Expected {\"values\": {...}}"})),
+        );
+    };
+
+    let result = gw.lock().await.submit_form(&form_id, values, None);
+    match result {
+        Ok(receipt) => {
+            if receipt.task_id.is_some() {
+                dispatch_tasks(gw.clone()).await;
+            }
+            (
+                StatusCode::OK,
+                axum::Json(serde_json::json!({
+                    "status": "submitted",
+                    "values": receipt.values,
+                    "task_id": receipt.task_id,
+                })),
+            )
+        }
+        Err(e) => {
+            let status = match &e {
+                FormSubmitError::NotFound => StatusCode::NOT_FOUND,
+                FormSubmitError::AlreadySubmitted => StatusCode::CONFLICT,
+                FormSubmitError::Expired => StatusCode::GONE,
+                FormSubmitError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+            let errors = match &e {
+                FormSubmitError::Invalid(errors) => errors.clone(),
+                _ => Vec::new(),
+            };
+            (
+                status,
+                axum::Json(serde_json::json!({
+                    "error": e.to_string(),
+                    "expired": e == FormSubmitError::Expired,
+                    "errors": errors,
+                })),
+            )
+        }
+    }
+}
+
+/// REST API: List session artifacts.
+async fn api_artifacts_handler(State(gw): State<SharedGateway>) -> impl IntoResponse {
+    let mut gw = gw.lock().await;
+    let artifacts = gw.artifacts();
+    axum::Json(serde_json::json!({
+        "total": artifacts.len(),
+        "artifacts": artifacts,
+    }))
+}
+
+/// REST API: Open or reveal a file artifact, or re-push a canvas artifact.
+///
+/// Body: `{"reveal": bool}` (optional). Stale file artifacts return `410 Gone`.
+async fn api_artifact_open_handler(
+    Path(id): Path<String>,
+    State(gw): State<SharedGateway>,
+    body: Option<axum::Json<serde_json::Value>>,
+) -> impl IntoResponse {
+    let Ok(artifact_id) = Uuid::parse_str(&id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({"error": "Invalid UUID"})),
+        );
+    };
+    let reveal = body
+        .as_ref()
+        .and_then(|b| b.get("reveal"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let mut gw = gw.lock().await;
+    let Some(artifact) = gw.artifacts().iter().find(|a| a.id == artifact_id).cloned() else {
+        return (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": "Artifact not found"})),
+        );
+    };
+
+    if let Some(message) = artifact.canvas_message() {
+        gw.broadcast(GatewayEvent::CanvasRepush {
+            artifact_id,
+            message,
+        });
+        return (
+            StatusCode::OK,
+            axum::Json(serde_json::json!({"status": "pushed"})),
+        );
+    }
+    if artifact.stale {
+        return (
+            StatusCode::GONE,
+            axum::Json(
+                serde_json::json!({"error": "Artifact file no longer exists", "stale": true}),
+            ),
+        );
+    }
+    match artifact.open(reveal) {
+        Ok(()) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!({"status": if reveal { "revealed" } else { "opened" }})),
+        ),
+        Err(e) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            axum::Json(serde_json::json!({"error": e})),
+        ),
+    }
+}
+
+/// REST API: Fetch the bytes of a tool attachment by handle, for display.
+///
+/// Expired or unknown handles return `404 Not Found`.
+async fn api_attachment_handler(Path(id): Path<String>) -> Response {
+    match crate::attachments::read(&id) {
+        Ok((handle, bytes)) => ([(CONTENT_TYPE, handle.mime_type)], bytes).into_response(),
+        Err(e) => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+/// REST API: List PTY sessions, running and recently exited.
+async fn api_pty_handler(State(gw): State<SharedGateway>) -> impl IntoResponse {
+    let sessions = gw.lock().await.pty().list();
+    axum::Json(serde_json::json!({
+        "running": sessions.iter().filter(|s| s.exit.is_none()).count(),
+        "sessions": sessions,
+    }))
+}
+
 /// Handle an individual WebSocket connection.
-async fn handle_socket(mut socket: WebSocket, gw: SharedGateway) {
+async fn handle_socket(mut socket: WebSocket, gw: SharedGateway, remote_addr: Option<SocketAddr>) {
     // Try to register the connection
     let conn_id = {
         let mut gw = gw.lock().await;
-        match gw.connections_mut().add_connection() {
+        match gw.connections_mut().add_connection_from(remote_addr) {
             Some(id) => id,
             None => {
                 // At capacity — send error and close
@@ -520,18 +2187,139 @@ async fn handle_socket(mut socket: WebSocket, gw: SharedGateway) {
                 if let Ok(json) = serde_json::to_string(&err) {
                     let _ = socket.send(WsMessage::Text(json.into())).await;
                 }
-                let _ = socket.close().await;
-                return;
+                let _ = socket.close().await;
+                return;
+            }
+        }
+    };
+
+    // Drain the broadcast channel into this connection's queue as events
+    // arrive, so a slow client only ever backs up its own queue.
+    let (mut events, queue, mut phase, mut disconnects, ping_every) = {
+        let gw = gw.lock().await;
+        (
+            gw.subscribe(),
+            Arc::new(EventQueue::new(gw.config().event_queue_capacity)),
+            gw.shutdown_phase(),
+            gw.disconnects.subscribe(),
+            Duration::from_secs(gw.config().ping_interval_secs),
+        )
+    };
+    // A client silent for two ping intervals has gone away without closing.
+    let mut pings = (!ping_every.is_zero())
+        .then(|| tokio::time::interval_at(tokio::time::Instant::now() + ping_every, ping_every));
+    let stale_after = chrono::Duration::from_std(ping_every * 2).unwrap_or(chrono::Duration::MAX);
+    let mut reason = DisconnectReason::Closed;
+    // A client connecting mid-shutdown is told straight away.
+    if *phase.borrow() != ShutdownPhase::Running {
+        phase.mark_changed();
+    }
+    let forwarder = {
+        let queue = Arc::clone(&queue);
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        queue.push(event);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(missed)) => queue.record_lag(missed),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    };
+    let mut authenticated = false;
+
+    // Message loop: client messages in, queued events out (once authenticated)
+    loop {
+        let ws_msg = tokio::select! {
+            msg = socket.recv() => match msg {
+                Some(Ok(m)) => m,
+                _ => break,
+            },
+            _ = queue.ready() => {
+                // Events queued before authentication are discarded.
+                let pending = queue.drain();
+                if !authenticated {
+                    continue;
+                }
+                let mut closed = false;
+                let mut sent = 0;
+                for msg in pending {
+                    if let Ok(json) = serde_json::to_string(&msg) {
+                        if socket.send(WsMessage::Text(json.into())).await.is_err() {
+                            closed = true;
+                            break;
+                        }
+                        sent += 1;
+                    }
+                }
+                gw.lock().await.connections_mut().record_sent(&conn_id, sent);
+                if closed {
+                    break;
+                }
+                continue;
+            }
+            _ = async {
+                match pings.as_mut() {
+                    Some(pings) => pings.tick().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                if gw.lock().await.connections().is_idle(&conn_id, stale_after) {
+                    tracing::info!(connection = %conn_id, "Closing stale gateway connection");
+                    reason = DisconnectReason::Stale;
+                    let _ = socket.close().await;
+                    break;
+                }
+                if socket.send(WsMessage::Ping(Default::default())).await.is_err() {
+                    break;
+                }
+                continue;
             }
-        }
-    };
-
-    // Message loop
-    while let Some(Ok(ws_msg)) = socket.recv().await {
+            changed = phase.changed() => {
+                // Keep serving while draining so approvals for running tasks
+                // still arrive; close once the gateway is done.
+                let current = *phase.borrow_and_update();
+                match current {
+                    ShutdownPhase::Draining { grace_secs } if changed.is_ok() => {
+                        let msg = ServerMessage::ShuttingDown { grace_secs };
+                        if let Ok(json) = serde_json::to_string(&msg)
+                            && socket.send(WsMessage::Text(json.into())).await.is_err()
+                        {
+                            break;
+                        }
+                        continue;
+                    }
+                    ShutdownPhase::Running if changed.is_ok() => continue,
+                    _ => {
+                        let _ = socket.close().await;
+                        break;
+                    }
+                }
+            }
+            closing = disconnects.recv() => match closing {
+                Ok((id, message)) if id == conn_id => {
+                    let msg = ServerMessage::AuthFailed { reason: message };
+                    if let Ok(json) = serde_json::to_string(&msg) {
+                        let _ = socket.send(WsMessage::Text(json.into())).await;
+                    }
+                    let _ = socket.close().await;
+                    reason = DisconnectReason::Kicked;
+                    break;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+                _ => continue,
+            },
+        };
         let text = match ws_msg {
             WsMessage::Text(t) => t.to_string(),
             WsMessage::Close(_) => break,
-            _ => continue,
+            _ => {
+                // Pongs and other frames show the client is still there.
+                gw.lock().await.connections_mut().touch(&conn_id);
+                continue;
+            }
         };
 
         let client_msg: ClientMessage = match serde_json::from_str(&text) {
@@ -550,23 +2338,51 @@ async fn handle_socket(mut socket: WebSocket, gw: SharedGateway) {
             }
         };
 
+        let submitted = matches!(
+            client_msg,
+            ClientMessage::SubmitTask { .. } | ClientMessage::SubmitForm { .. }
+        );
+        let mismatch = match &client_msg {
+            ClientMessage::Authenticate {
+                protocol_version: Some(version),
+                ..
+            } => version_mismatch(version),
+            _ => None,
+        };
         let response = {
             let mut gw = gw.lock().await;
-            gw.connections_mut().touch(&conn_id);
-            gw.handle_client_message(client_msg, conn_id)
+            gw.connections_mut().record_received(&conn_id);
+            let response = gw.handle_client_message(client_msg, conn_id);
+            gw.connections_mut().record_sent(&conn_id, 1);
+            authenticated = gw.connections().is_authenticated(&conn_id);
+            queue.set_topics(gw.connections().topics(&conn_id));
+            queue.set_pty_sessions(gw.connections().pty_sessions(&conn_id));
+            response
         };
+        if submitted {
+            dispatch_tasks(gw.clone()).await;
+        }
 
         if let Ok(json) = serde_json::to_string(&response)
             && socket.send(WsMessage::Text(json.into())).await.is_err()
         {
             break;
         }
+        if let ServerMessage::Authenticated { .. } = response
+            && let Some(warning) = mismatch
+            && let Ok(json) = serde_json::to_string(&warning)
+            && socket.send(WsMessage::Text(json.into())).await.is_err()
+        {
+            break;
+        }
     }
 
-    // Cleanup
+    // Cleanup. PTY sessions this client was attached to keep running.
+    forwarder.abort();
     {
         let mut gw = gw.lock().await;
-        gw.connections_mut().remove_connection(&conn_id);
+        gw.connections_mut()
+            .remove_connection_with(&conn_id, reason);
         gw.broadcast(GatewayEvent::Disconnected {
             connection_id: conn_id,
         });
@@ -710,6 +2526,9 @@ async fn api_meeting_stop_handler(State(gw): State<SharedGateway>) -> impl IntoR
                 "duration_secs": result.duration_secs,
                 "transcript_length": result.transcript.len(),
                 "notes_saved": result.notes_saved,
+                "segments": result.segments,
+                "speakers": result.speakers(),
+                "transcription_mode": result.transcription_mode,
             })),
         ),
         Err(e) => (
@@ -740,6 +2559,8 @@ async fn api_meeting_status_handler(State(gw): State<SharedGateway>) -> impl Int
             "title": status.title,
             "started_at": status.started_at,
             "elapsed_secs": status.elapsed_secs,
+            "live_segments": status.live_transcript.as_ref().map(|t| t.recent(20)),
+            "transcription_mode": status.live_transcript.as_ref().map(|t| &t.mode),
         })),
         None => axum::Json(serde_json::json!({
             "active": false,
@@ -748,27 +2569,117 @@ async fn api_meeting_status_handler(State(gw): State<SharedGateway>) -> impl Int
     }
 }
 
+/// Shut the gateway down gracefully.
+///
+/// Clients receive `ShuttingDown`, new tasks are refused and queued ones are
+/// cancelled. Running tasks get up to `grace` to finish before they are
+/// cancelled too. PTY sessions are then ended so their journals are complete,
+/// and connections are closed. Returns the number of tasks cancelled after
+/// the grace period.
+pub async fn shutdown(gw: SharedGateway, grace: Duration) -> usize {
+    let deadline = tokio::time::Instant::now() + grace;
+    let pty = {
+        let mut gw = gw.lock().await;
+        gw.begin_shutdown(grace);
+        gw.pty()
+    };
+    while gw.lock().await.running_tasks() > 0 && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let cancelled = gw.lock().await.tasks_mut().cancel_running();
+    if cancelled > 0 {
+        tracing::warn!(
+            cancelled,
+            "Cancelling tasks still running after the grace period"
+        );
+        // Give runners a moment to observe cancellation and report it.
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while gw.lock().await.running_tasks() > 0 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    for session in pty.list().into_iter().filter(|s| s.exit.is_none()) {
+        if pty.kill(&session.id).is_ok() {
+            let _ = pty.wait(&session.id, Duration::from_secs(2)).await;
+        }
+    }
+
+    gw.lock().await.shutdown.send_replace(ShutdownPhase::Closed);
+    cancelled
+}
+
 /// Start the gateway server on the configured address.
 ///
-/// This is an async function that runs until cancelled.
+/// Runs until cancelled, or until [`shutdown`] has closed the gateway.
 pub async fn run(gw: SharedGateway) -> Result<(), std::io::Error> {
-    let (host, port) = {
+    let (host, port, mut phase) = {
         let gw = gw.lock().await;
-        (gw.config().host.clone(), gw.config().port)
+        (
+            gw.config().host.clone(),
+            gw.config().port,
+            gw.shutdown_phase(),
+        )
     };
-    let app = router(gw);
+    let app = router(gw).into_make_service_with_connect_info::<SocketAddr>();
     let addr = format!("{}:{}", host, port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            let _ = phase.wait_for(|p| *p == ShutdownPhase::Closed).await;
+        })
+        .await?;
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gateway::tasks::{TaskProgress, TaskSummary};
+    use crate::safety::ApprovalDecision;
     use axum::body::Body;
+    use tokio_util::sync::CancellationToken;
     use tower::ServiceExt;
 
+    /// Runner that reports a tool call and echoes the task text back.
+    struct EchoRunner;
+
+    #[async_trait::async_trait]
+    impl TaskRunner for EchoRunner {
+        async fn run(
+            &self,
+            request: TaskRequest,
+            progress: TaskProgress,
+            cancel: CancellationToken,
+        ) -> Result<crate::agent::TaskResult, String> {
+            progress.send(TaskUpdate::ToolStarted {
+                tool_name: "file_read".into(),
+                summary: None,
+            });
+            if request.text == "wait" {
+                cancel.cancelled().await;
+            }
+            Ok(crate::agent::TaskResult {
+                task_id: request.task_id,
+                success: true,
+                response: format!("echo: {}", request.text),
+                iterations: 1,
+                total_usage: Default::default(),
+                total_cost: Default::default(),
+                artifacts: Vec::new(),
+                subtasks: Vec::new(),
+                grants: Vec::new(),
+            })
+        }
+    }
+
+    fn server_with_runner(config: GatewayConfig) -> GatewayServer {
+        let mut server = GatewayServer::new(config);
+        server.set_task_runner(Arc::new(EchoRunner));
+        server
+    }
+
     #[test]
     fn test_server_construction() {
         let config = GatewayConfig::default();
@@ -816,6 +2727,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_subscribe_and_unsubscribe_topics() {
+        use crate::gateway::EventTopic;
+        let mut server = GatewayServer::new(GatewayConfig::default());
+        let conn_id = server.connections_mut().add_connection().unwrap();
+
+        let response = server.handle_client_message(
+            ClientMessage::Unsubscribe {
+                topics: EventTopic::ALL.to_vec(),
+            },
+            conn_id,
+        );
+        assert!(matches!(response, ServerMessage::Subscriptions { topics } if topics.is_empty()));
+
+        let response = server.handle_client_message(
+            ClientMessage::Subscribe {
+                topics: vec![EventTopic::Metrics, EventTopic::Approvals],
+            },
+            conn_id,
+        );
+        match response {
+            ServerMessage::Subscriptions { topics } => {
+                assert_eq!(topics, vec![EventTopic::Approvals, EventTopic::Metrics])
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let queue = EventQueue::new(8);
+        queue.set_topics(server.connections().topics(&conn_id));
+        assert!(!queue.push(GatewayEvent::StreamToken { token: "t".into() }));
+        assert!(queue.push(GatewayEvent::ConfigSnapshot {
+            config_json: "{}".into()
+        }));
+    }
+
     #[test]
     fn test_server_uptime() {
         let server = GatewayServer::new(GatewayConfig::default());
@@ -891,12 +2837,19 @@ mod tests {
         let resp = server.handle_client_message(
             ClientMessage::Authenticate {
                 token: "secret".into(),
+                protocol_version: None,
+                client_name: None,
+                client_type: None,
             },
             conn_id,
         );
         match resp {
-            ServerMessage::Authenticated { connection_id } => {
+            ServerMessage::Authenticated {
+                connection_id,
+                protocol_version,
+            } => {
                 assert_eq!(connection_id, conn_id);
+                assert_eq!(protocol_version, PROTOCOL_VERSION);
             }
             _ => panic!("Expected Authenticated, got {:?}", resp),
         }
@@ -915,6 +2868,9 @@ mod tests {
         let resp = server.handle_client_message(
             ClientMessage::Authenticate {
                 token: "wrong".into(),
+                protocol_version: None,
+                client_name: None,
+                client_type: None,
             },
             conn_id,
         );
@@ -972,7 +2928,9 @@ mod tests {
 
         let resp = server.handle_client_message(
             ClientMessage::SubmitTask {
-                description: "test task".into(),
+                text: "test task".into(),
+                workspace: None,
+                options: TaskOptions::default(),
             },
             conn_id,
         );
@@ -986,14 +2944,16 @@ mod tests {
 
     #[test]
     fn test_handle_submit_task_authenticated() {
-        let mut server = GatewayServer::new(GatewayConfig::default());
+        let mut server = server_with_runner(GatewayConfig::default());
         let conn_id = server.connections_mut().add_connection().unwrap();
         // Open mode — auto-authenticated by validate("")
         server.connections_mut().authenticate(&conn_id);
 
         let resp = server.handle_client_message(
             ClientMessage::SubmitTask {
-                description: "build feature X".into(),
+                text: "build feature X".into(),
+                workspace: None,
+                options: TaskOptions::default(),
             },
             conn_id,
         );
@@ -1011,27 +2971,661 @@ mod tests {
 
     #[test]
     fn test_handle_cancel_task() {
-        let mut server = GatewayServer::new(GatewayConfig::default());
+        let mut server = server_with_runner(GatewayConfig::default());
         let conn_id = server.connections_mut().add_connection().unwrap();
         server.connections_mut().authenticate(&conn_id);
-        let task_id = Uuid::new_v4();
+        let (task_id, _) = server
+            .submit_task("queued".into(), None, TaskOptions::default(), conn_id)
+            .unwrap();
+        let mut rx = server.subscribe();
 
         let resp = server.handle_client_message(ClientMessage::CancelTask { task_id }, conn_id);
-        match resp {
-            ServerMessage::Event {
-                event:
-                    GatewayEvent::TaskCompleted {
-                        task_id: tid,
-                        success,
-                        summary,
-                    },
+        assert!(matches!(
+            resp,
+            ServerMessage::CancelAck { task_id: tid, accepted: true } if tid == task_id
+        ));
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            GatewayEvent::TaskUpdate {
+                update: TaskUpdate::Cancelled,
+                ..
+            }
+        ));
+        match rx.try_recv().unwrap() {
+            GatewayEvent::TaskCompleted {
+                task_id: tid,
+                success,
+                summary,
             } => {
                 assert_eq!(tid, task_id);
                 assert!(!success);
                 assert!(summary.contains("Cancelled"));
             }
-            _ => panic!("Expected TaskCompleted with cancel"),
+            other => panic!("Expected TaskCompleted with cancel, got {:?}", other),
+        }
+
+        let resp = server.handle_client_message(
+            ClientMessage::CancelTask {
+                task_id: Uuid::new_v4(),
+            },
+            conn_id,
+        );
+        assert!(matches!(
+            resp,
+            ServerMessage::CancelAck {
+                accepted: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_submit_task_requires_task_scope_and_runner() {
+        let config = GatewayConfig {
+            auth_tokens: vec!["viewer".into()],
+            task_tokens: vec!["operator".into()],
+            ..GatewayConfig::default()
+        };
+        let mut server = GatewayServer::new(config);
+        let submit = || ClientMessage::SubmitTask {
+            text: "deploy".into(),
+            workspace: None,
+            options: TaskOptions::default(),
+        };
+
+        let viewer = server.connections_mut().add_connection().unwrap();
+        server.handle_client_message(
+            ClientMessage::Authenticate {
+                token: "viewer".into(),
+                protocol_version: None,
+                client_name: None,
+                client_type: None,
+            },
+            viewer,
+        );
+        match server.handle_client_message(submit(), viewer) {
+            ServerMessage::Event {
+                event: GatewayEvent::Error { code, .. },
+            } => assert_eq!(code, "FORBIDDEN"),
+            other => panic!("Expected FORBIDDEN, got {:?}", other),
+        }
+        assert!(matches!(
+            server.handle_client_message(ClientMessage::GetStatus, viewer),
+            ServerMessage::StatusResponse { .. }
+        ));
+
+        let operator = server.connections_mut().add_connection().unwrap();
+        server.handle_client_message(
+            ClientMessage::Authenticate {
+                token: "operator".into(),
+                protocol_version: None,
+                client_name: None,
+                client_type: None,
+            },
+            operator,
+        );
+        // No runner attached yet.
+        match server.handle_client_message(submit(), operator) {
+            ServerMessage::Event {
+                event: GatewayEvent::Error { code, .. },
+            } => assert_eq!(code, "TASK_REJECTED"),
+            other => panic!("Expected TASK_REJECTED, got {:?}", other),
+        }
+        server.set_task_runner(Arc::new(EchoRunner));
+        assert!(matches!(
+            server.handle_client_message(submit(), operator),
+            ServerMessage::Event {
+                event: GatewayEvent::TaskSubmitted { .. }
+            }
+        ));
+    }
+
+    fn pairing_request(offer: &PairingOffer, name: &str) -> DevicePairingRequest {
+        DevicePairingRequest {
+            response: crate::pairing::PairingResponse {
+                challenge_id: offer.challenge.challenge_id,
+                device_id: Uuid::new_v4(),
+                device_name: name.into(),
+                public_key: String::new(),
+                response_hmac: crate::pairing::compute_hmac(
+                    offer.secret.as_bytes(),
+                    offer.challenge.nonce.as_bytes(),
+                ),
+            },
+            platform: "android".into(),
+        }
+    }
+
+    #[test]
+    fn test_device_pairing_scopes_and_revocation() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = GatewayConfig {
+            task_tokens: vec!["operator".into()],
+            ..GatewayConfig::default()
+        };
+        let mut server = GatewayServer::new(config);
+        server.set_state_dir(dir.path()).unwrap();
+        let mut revoked = server.disconnects.subscribe();
+
+        let operator = server.connections_mut().add_connection().unwrap();
+        server.handle_client_message(
+            ClientMessage::Authenticate {
+                token: "operator".into(),
+                protocol_version: None,
+                client_name: None,
+                client_type: None,
+            },
+            operator,
+        );
+        let offer = match server.handle_client_message(
+            ClientMessage::CreatePairing {
+                scope: AuthScope::Status,
+            },
+            operator,
+        ) {
+            ServerMessage::PairingOffer { offer, qr_png } => {
+                assert!(!qr_png.is_empty());
+                offer
+            }
+            other => panic!("Expected PairingOffer, got {:?}", other),
+        };
+
+        let request = pairing_request(&offer, "Pixel");
+        let (device, grant) = server.complete_pairing(&request).unwrap();
+        // Offers are single-use.
+        assert_eq!(
+            server.complete_pairing(&request).unwrap_err(),
+            PairingError::UnknownChallenge
+        );
+
+        let phone = server.connections_mut().add_connection().unwrap();
+        assert!(matches!(
+            server.handle_client_message(
+                ClientMessage::Authenticate {
+                    token: grant.token,
+                    protocol_version: None,
+                    client_name: None,
+                    client_type: None,
+                },
+                phone
+            ),
+            ServerMessage::Authenticated { .. }
+        ));
+        // Status devices see metrics but cannot decide approvals or pair others.
+        assert!(matches!(
+            server.handle_client_message(ClientMessage::GetStatus, phone),
+            ServerMessage::StatusResponse { .. }
+        ));
+        for msg in [
+            ClientMessage::ApprovalDecision {
+                approval_id: Uuid::new_v4(),
+                approved: true,
+                reason: None,
+            },
+            ClientMessage::ListDevices,
+        ] {
+            match server.handle_client_message(msg, phone) {
+                ServerMessage::Event {
+                    event: GatewayEvent::Error { code, .. },
+                } => assert_eq!(code, "FORBIDDEN"),
+                other => panic!("Expected FORBIDDEN, got {:?}", other),
+            }
+        }
+
+        // Paired devices survive a restart.
+        let mut restarted = GatewayServer::new(GatewayConfig::default());
+        restarted.set_state_dir(dir.path()).unwrap();
+        assert_eq!(restarted.paired_devices().len(), 1);
+
+        let device_id = device.identity.device_id;
+        assert!(matches!(
+            server.handle_client_message(ClientMessage::RevokeDevice { device_id }, operator),
+            ServerMessage::Devices { devices } if devices.is_empty()
+        ));
+        assert_eq!(revoked.try_recv().unwrap().0, phone);
+        assert!(!server.connections().is_authenticated(&phone));
+
+        let actions: Vec<String> = server
+            .audit_entries()
+            .iter()
+            .filter_map(|e| match &e.event {
+                AuditEvent::DevicePairing { action, .. } => Some(action.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(actions, ["offered", "paired", "rejected", "revoked"]);
+    }
+
+    #[tokio::test]
+    async fn test_pairing_endpoints_require_task_token() {
+        let config = GatewayConfig {
+            auth_tokens: vec!["viewer".into()],
+            task_tokens: vec!["operator".into()],
+            ..GatewayConfig::default()
+        };
+        let gw = make_shared_gateway(config);
+        let app = router(gw.clone());
+        let create = |token: &str| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/pairing")
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"scope":"approvals"}"#))
+                .unwrap()
+        };
+
+        let resp = ServiceExt::<axum::http::Request<Body>>::oneshot(app.clone(), create("viewer"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 403);
+
+        let resp =
+            ServiceExt::<axum::http::Request<Body>>::oneshot(app.clone(), create("operator"))
+                .await
+                .unwrap();
+        assert_eq!(resp.status(), 200);
+        let body = axum::body::to_bytes(resp.into_body(), 1_000_000)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let offer: PairingOffer = serde_json::from_value(json["offer"].clone()).unwrap();
+        assert_eq!(offer.scope, AuthScope::Read);
+
+        let pair = axum::http::Request::builder()
+            .method("POST")
+            .uri("/api/pair")
+            .header("Content-Type", "application/json")
+            .body(Body::from(
+                serde_json::to_string(&pairing_request(&offer, "iPad")).unwrap(),
+            ))
+            .unwrap();
+        let resp = ServiceExt::<axum::http::Request<Body>>::oneshot(app, pair)
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let body = axum::body::to_bytes(resp.into_body(), 10_000)
+            .await
+            .unwrap();
+        let grant: DeviceGrant = serde_json::from_slice(&body).unwrap();
+        assert_eq!(grant.scope, AuthScope::Read);
+        assert_eq!(gw.lock().await.paired_devices().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_clients_listed_and_disconnected_by_operator() {
+        let config = GatewayConfig {
+            auth_tokens: vec!["viewer".into()],
+            task_tokens: vec!["operator".into()],
+            ..GatewayConfig::default()
+        };
+        let gw = make_shared_gateway(config);
+        let (laptop, mut closing) = {
+            let mut server = gw.lock().await;
+            let addr = "10.0.0.5:41000".parse().unwrap();
+            let laptop = server
+                .connections_mut()
+                .add_connection_from(Some(addr))
+                .unwrap();
+            server.handle_client_message(
+                ClientMessage::Authenticate {
+                    token: "viewer".into(),
+                    protocol_version: None,
+                    client_name: Some("Laptop".into()),
+                    client_type: Some("dashboard".into()),
+                },
+                laptop,
+            );
+            (laptop, server.disconnects.subscribe())
+        };
+        let app = router(gw.clone());
+        let request = |method: &str, uri: String, token: &str| {
+            axum::http::Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let resp = ServiceExt::<axum::http::Request<Body>>::oneshot(
+            app.clone(),
+            request("GET", "/api/clients".into(), "viewer"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), 403);
+
+        let resp = ServiceExt::<axum::http::Request<Body>>::oneshot(
+            app.clone(),
+            request("GET", "/api/clients".into(), "operator"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), 200);
+        let body = axum::body::to_bytes(resp.into_body(), 100_000)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let clients: Vec<ConnectionInfo> = serde_json::from_value(json["clients"].clone()).unwrap();
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].label(), "Laptop (dashboard)");
+        assert_eq!(
+            clients[0].remote_addr.unwrap().to_string(),
+            "10.0.0.5:41000"
+        );
+        assert!(
+            clients[0]
+                .token_id
+                .as_deref()
+                .unwrap()
+                .starts_with("token:")
+        );
+        assert_eq!(clients[0].scope, AuthScope::Read);
+
+        let resp = ServiceExt::<axum::http::Request<Body>>::oneshot(
+            app,
+            request("DELETE", format!("/api/clients/{}", laptop), "operator"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), 200);
+        assert_eq!(closing.try_recv().unwrap().0, laptop);
+
+        let server = gw.lock().await;
+        assert!(server.audit_entries().iter().any(|e| matches!(
+            &e.event,
+            AuditEvent::ClientDisconnected { connection_id, client, .. }
+                if *connection_id == laptop && client == "Laptop (dashboard)"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_pty_streams_are_scoped_to_the_connection() {
+        let config = GatewayConfig {
+            auth_tokens: vec!["viewer".into()],
+            task_tokens: vec!["operator".into()],
+            ..GatewayConfig::default()
+        };
+        let mut server = GatewayServer::new(config);
+        let session = server
+            .pty()
+            .spawn(crate::gateway::PtySpawn {
+                command: "cat".into(),
+                cwd: std::env::temp_dir(),
+                reason: None,
+                journal_dir: None,
+                cols: 80,
+                rows: 24,
+            })
+            .unwrap();
+        let attach = |server: &mut GatewayServer, token: &str| {
+            let conn = server.connections_mut().add_connection().unwrap();
+            server.handle_client_message(
+                ClientMessage::Authenticate {
+                    token: token.into(),
+                    protocol_version: None,
+                    client_name: None,
+                    client_type: None,
+                },
+                conn,
+            );
+            let stream = match server.handle_client_message(
+                ClientMessage::PtyOpenStream {
+                    session_id: session.id,
+                },
+                conn,
+            ) {
+                ServerMessage::PtyStream { stream } => stream,
+                other => panic!("Expected PtyStream, got {:?}", other),
+            };
+            let reply = server.handle_client_message(
+                ClientMessage::PtyAttach {
+                    token: stream.token.clone(),
+                },
+                conn,
+            );
+            (conn, stream, reply)
+        };
+        let input = |conn| {
+            (
+                ClientMessage::PtyInput {
+                    session_id: session.id,
+                    data: "hi\n".into(),
+                },
+                conn,
+            )
+        };
+
+        let (viewer, stream, reply) = attach(&mut server, "viewer");
+        assert!(!stream.writable);
+        assert!(matches!(
+            reply,
+            ServerMessage::PtyAttached {
+                writable: false,
+                ..
+            }
+        ));
+        let (msg, conn) = input(viewer);
+        match server.handle_client_message(msg, conn) {
+            ServerMessage::Event {
+                event: GatewayEvent::Error { code, .. },
+            } => assert_eq!(code, "FORBIDDEN"),
+            other => panic!("Expected FORBIDDEN, got {:?}", other),
+        }
+        // A token works once.
+        let other = server.connections_mut().add_connection().unwrap();
+        server.connections_mut().authenticate(&other);
+        assert!(matches!(
+            server.handle_client_message(
+                ClientMessage::PtyAttach {
+                    token: stream.token
+                },
+                other
+            ),
+            ServerMessage::Event {
+                event: GatewayEvent::Error { .. }
+            }
+        ));
+
+        let (operator, _, _) = attach(&mut server, "operator");
+        let (msg, conn) = input(operator);
+        assert!(matches!(
+            server.handle_client_message(msg, conn),
+            ServerMessage::PtyAck { accepted: true, .. }
+        ));
+
+        // Detaching leaves the process running.
+        assert!(matches!(
+            server.handle_client_message(
+                ClientMessage::PtyDetach {
+                    session_id: session.id
+                },
+                operator
+            ),
+            ServerMessage::PtyAck { accepted: true, .. }
+        ));
+        server.connections_mut().remove_connection(&viewer);
+        assert_eq!(server.pty().running_count(), 1);
+        server.pty().kill(&session.id).unwrap();
+    }
+
+    #[test]
+    fn test_reload_swaps_tokens_without_dropping_connections() {
+        let config = GatewayConfig {
+            auth_tokens: vec!["old".into()],
+            ..GatewayConfig::default()
+        };
+        let mut server = GatewayServer::new(config.clone());
+        server.set_agent_config(&AgentConfig {
+            gateway: Some(config.clone()),
+            ..Default::default()
+        });
+        let conn_id = server.connections_mut().add_connection().unwrap();
+        let response = server.handle_client_message(
+            ClientMessage::Authenticate {
+                token: "old".into(),
+                protocol_version: None,
+                client_name: None,
+                client_type: None,
+            },
+            conn_id,
+        );
+        assert!(matches!(response, ServerMessage::Authenticated { .. }));
+
+        let mut rx = server.subscribe();
+        let report = server.reload(&AgentConfig {
+            gateway: Some(GatewayConfig {
+                auth_tokens: vec!["new".into()],
+                port: 9000,
+                max_queued_tasks: 2,
+                ..config
+            }),
+            ..Default::default()
+        });
+        assert_eq!(
+            report.applied,
+            vec!["gateway.auth_tokens", "gateway.max_queued_tasks"]
+        );
+        assert_eq!(report.deferred, vec!["gateway.port"]);
+        assert!(server.auth().validate("new"));
+        assert!(!server.auth().validate("old"));
+        assert_eq!(server.config().port, 8080);
+        assert!(server.connections().is_authenticated(&conn_id));
+        assert_eq!(server.last_reload(), Some(&report));
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            GatewayEvent::ConfigReloaded { .. }
+        ));
+
+        let failed = server.reload_from_source();
+        assert_eq!(failed.errors.len(), 1);
+        assert!(server.auth().validate("new"));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_queue_and_cancels_after_grace() {
+        let gw: SharedGateway = Arc::new(Mutex::new(server_with_runner(GatewayConfig::default())));
+        let conn_id = Uuid::new_v4();
+        let mut phase = gw.lock().await.shutdown_phase();
+        let (running, queued) = {
+            let mut server = gw.lock().await;
+            let (running, _) = server
+                .submit_task("wait".into(), None, TaskOptions::default(), conn_id)
+                .unwrap();
+            let (queued, _) = server
+                .submit_task("queued".into(), None, TaskOptions::default(), conn_id)
+                .unwrap();
+            (running, queued)
+        };
+        dispatch_tasks(gw.clone()).await;
+        let mut rx = gw.lock().await.subscribe();
+
+        let cancelled = shutdown(gw.clone(), Duration::from_millis(200)).await;
+        assert_eq!(cancelled, 1);
+        assert_eq!(*phase.borrow_and_update(), ShutdownPhase::Closed);
+
+        let mut completed = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let GatewayEvent::TaskCompleted { task_id, .. } = event {
+                completed.push(task_id);
+            }
+        }
+        assert_eq!(completed, vec![queued, running]);
+
+        let mut server = gw.lock().await;
+        assert_eq!(server.running_tasks(), 0);
+        let err = server
+            .submit_task("late".into(), None, TaskOptions::default(), conn_id)
+            .unwrap_err();
+        assert!(err.contains("shutting down"));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_runs_tasks_in_order_and_streams_progress() {
+        let gw: SharedGateway = Arc::new(Mutex::new(server_with_runner(GatewayConfig::default())));
+        let conn_id = Uuid::new_v4();
+        let mut rx = gw.lock().await.subscribe();
+        let (blocker, second) = {
+            let mut server = gw.lock().await;
+            let (blocker, _) = server
+                .submit_task("wait".into(), None, TaskOptions::default(), conn_id)
+                .unwrap();
+            let (second, position) = server
+                .submit_task("second".into(), None, TaskOptions::default(), conn_id)
+                .unwrap();
+            assert_eq!(position, 1);
+            (blocker, second)
+        };
+
+        dispatch_tasks(gw.clone()).await;
+        {
+            let server = gw.lock().await;
+            assert_eq!(server.running_tasks(), 1);
+            assert_eq!(server.queued_tasks(), 1);
+        }
+        assert!(gw.lock().await.cancel_task(&blocker));
+
+        let mut updates = Vec::new();
+        let deadline = std::time::Duration::from_secs(5);
+        while let Ok(Ok(event)) = tokio::time::timeout(deadline, rx.recv()).await {
+            if let GatewayEvent::TaskUpdate { task_id, update } = event {
+                let done = task_id == second && matches!(update, TaskUpdate::Completed { .. });
+                updates.push((task_id, update));
+                if done {
+                    break;
+                }
+            }
         }
+
+        assert!(updates.contains(&(blocker, TaskUpdate::Cancelled)));
+        assert!(updates.contains(&(
+            second,
+            TaskUpdate::ToolStarted {
+                tool_name: "file_read".into(),
+                summary: None
+            }
+        )));
+        let summary = updates.iter().find_map(|(id, u)| match u {
+            TaskUpdate::Completed { summary } if *id == second => Some(summary.clone()),
+            _ => None,
+        });
+        let summary: TaskSummary = summary.expect("second task completed");
+        assert_eq!(summary.response, "echo: second");
+        let cancelled_at = updates
+            .iter()
+            .position(|u| *u == (blocker, TaskUpdate::Cancelled))
+            .unwrap();
+        let second_started = updates
+            .iter()
+            .position(|u| *u == (second, TaskUpdate::Started))
+            .unwrap();
+        assert!(cancelled_at < second_started);
+    }
+
+    #[tokio::test]
+    async fn test_task_progress_approval_round_trip() {
+        let gw: SharedGateway = Arc::new(Mutex::new(GatewayServer::new(GatewayConfig::default())));
+        let progress = {
+            let server = gw.lock().await;
+            TaskProgress::new(Uuid::new_v4(), server.event_sender(), gw.clone())
+        };
+        let waiter = tokio::spawn({
+            let progress = progress.clone();
+            async move {
+                progress
+                    .request_approval("shell_exec", "Run cargo test", RiskLevel::Execute)
+                    .await
+            }
+        });
+        let approval_id = loop {
+            if let Some(a) = gw.lock().await.pending_approvals().first() {
+                break a.id;
+            }
+            tokio::task::yield_now().await;
+        };
+        assert!(gw.lock().await.resolve_approval(&approval_id, true));
+        assert_eq!(waiter.await.unwrap(), ApprovalDecision::Approve);
     }
 
     // --- StatusProvider wiring tests ---
@@ -1128,6 +3722,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_focus_status_reports_open_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut server = GatewayServer::new(GatewayConfig::default());
+        assert!(server.focus_status().is_none());
+        server.set_focus_workspace(dir.path());
+        assert!(server.focus_status().is_none());
+
+        let mut focus = crate::focus::FocusState::default();
+        focus.begin("deep work", 25, Utc::now());
+        focus.save(dir.path()).unwrap();
+        let status = server.focus_status().unwrap();
+        assert_eq!(status["task"], "deep work");
+        assert!(
+            status["status"]
+                .as_str()
+                .unwrap()
+                .starts_with("In a focus block")
+        );
+    }
+
     #[test]
     fn test_status_provider_can_be_replaced() {
         let mut server = GatewayServer::new(GatewayConfig::default());
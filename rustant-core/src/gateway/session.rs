@@ -21,6 +21,9 @@ pub struct GatewaySession {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub connection_id: Uuid,
+    /// Workspace the session's tasks run in, as submitted (a path or a
+    /// workspace name); `None` means the gateway's default workspace.
+    pub workspace: Option<String>,
 }
 
 /// Manages gateway sessions.
@@ -36,6 +39,11 @@ impl SessionManager {
 
     /// Create a new session for a connection.
     pub fn create_session(&mut self, connection_id: Uuid) -> Uuid {
+        self.create_session_in(connection_id, None)
+    }
+
+    /// Create a new session for a connection, working in `workspace`.
+    pub fn create_session_in(&mut self, connection_id: Uuid, workspace: Option<String>) -> Uuid {
         let now = Utc::now();
         let session_id = Uuid::new_v4();
         self.sessions.insert(
@@ -46,6 +54,7 @@ impl SessionManager {
                 created_at: now,
                 updated_at: now,
                 connection_id,
+                workspace,
             },
         );
         session_id
@@ -134,7 +143,11 @@ mod tests {
         let session = mgr.get(&session_id).unwrap();
         assert_eq!(session.state, SessionState::Active);
         assert_eq!(session.connection_id, conn_id);
+        assert_eq!(session.workspace, None);
         assert_eq!(mgr.active_count(), 1);
+
+        let acme = mgr.create_session_in(conn_id, Some("acme".to_string()));
+        assert_eq!(mgr.get(&acme).unwrap().workspace.as_deref(), Some("acme"));
     }
 
     #[test]
@@ -0,0 +1,859 @@
+//! Task submission over the gateway.
+//!
+//! Clients with the `tasks` scope send `ClientMessage::SubmitTask`; the
+//! gateway queues the request, starts it once a slot is free (bounded by
+//! `GatewayConfig::max_concurrent_tasks`), and streams `TaskUpdate` events
+//! back while the host's [`TaskRunner`] executes it. `CancelTask` removes a
+//! queued task or trips the running task's `CancellationToken`.
+
+use super::events::GatewayEvent;
+use super::forms::{FormOutcome, PendingForm};
+use super::server::{PendingApproval, SharedGateway};
+use crate::agent::{AgentCallback, TaskResult};
+use crate::approval_timeout::{ExpiredApproval, ResolvedPolicy, escalation_message};
+use crate::canvas::FormSpec;
+use crate::cost_preflight::{CostDecision, CostReport, PlanEstimate};
+use crate::explanation::DecisionExplanation;
+use crate::safety::{ActionRequest, ApprovalDecision};
+use crate::types::{AgentStatus, CostEstimate, RiskLevel, TokenUsage, ToolOutput};
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, oneshot};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Per-task options supplied with `SubmitTask`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TaskOptions {
+    /// Override the agent's maximum iterations for this task.
+    #[serde(default)]
+    pub max_iterations: Option<usize>,
+}
+
+/// A task accepted by the gateway, waiting for or holding a runner slot.
+#[derive(Debug, Clone)]
+pub struct TaskRequest {
+    pub task_id: Uuid,
+    pub text: String,
+    pub workspace: Option<String>,
+    pub options: TaskOptions,
+    /// Connection that submitted the task.
+    pub connection_id: Uuid,
+}
+
+/// Summary of a finished task, derived from the agent's `TaskResult`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TaskSummary {
+    pub success: bool,
+    pub response: String,
+    pub iterations: usize,
+    pub total_tokens: usize,
+    pub total_cost_usd: f64,
+    /// Titles of artifacts the task produced.
+    #[serde(default)]
+    pub artifacts: Vec<String>,
+    /// Approvals that expired without a decision, so their steps were not done.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_approvals: Vec<ExpiredApproval>,
+}
+
+impl From<&TaskResult> for TaskSummary {
+    fn from(result: &TaskResult) -> Self {
+        Self {
+            success: result.success,
+            response: result.response.clone(),
+            iterations: result.iterations,
+            total_tokens: result.total_usage.total(),
+            total_cost_usd: result.total_cost.total(),
+            artifacts: result.artifacts.iter().map(|a| a.title.clone()).collect(),
+            skipped_approvals: Vec::new(),
+        }
+    }
+}
+
+/// Lifecycle stage of a gateway task, streamed as `GatewayEvent::TaskUpdate`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum TaskUpdate {
+    /// Queued; `position` is the number of tasks ahead of it (0 = next).
+    Accepted { position: usize },
+    /// A runner slot was assigned and the agent is starting.
+    Started,
+    /// The agent is generating a plan.
+    Planning,
+    /// The plan's preflight cost estimate. Gateway tasks are unattended, so
+    /// the plan runs under a budget derived from it.
+    CostEstimated { cost_usd: f64, llm_calls: usize },
+    /// The plan finished; actual cost against the estimate.
+    CostReported {
+        estimated_usd: f64,
+        actual_usd: f64,
+        accuracy: f64,
+        budget_usd: Option<f64>,
+    },
+    /// A tool call began. `summary` is the call's one-line, redacted
+    /// description, e.g. `file_read src/main.rs L1-40`.
+    ToolStarted {
+        tool_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        summary: Option<String>,
+    },
+    /// A tool call finished.
+    ToolFinished {
+        tool_name: String,
+        success: bool,
+        duration_ms: u64,
+    },
+    /// Partial assistant text (streamed tokens or a full message).
+    AssistantText { text: String },
+    /// The task finished.
+    Completed { summary: TaskSummary },
+    /// The runner returned an error.
+    Failed { error: String },
+    /// The task was cancelled by a client.
+    Cancelled,
+}
+
+/// Executes gateway tasks. Implemented by the host (CLI, desktop app) that
+/// owns provider configuration and the tool registry.
+#[async_trait]
+pub trait TaskRunner: Send + Sync {
+    /// Run a task to completion, reporting progress through `progress` and
+    /// stopping promptly once `cancel` is triggered.
+    async fn run(
+        &self,
+        request: TaskRequest,
+        progress: TaskProgress,
+        cancel: CancellationToken,
+    ) -> Result<TaskResult, String>;
+}
+
+/// Progress sink handed to a [`TaskRunner`] for one task.
+#[derive(Clone)]
+pub struct TaskProgress {
+    task_id: Uuid,
+    event_tx: broadcast::Sender<GatewayEvent>,
+    gateway: SharedGateway,
+    /// Approvals of this task that expired without a decision.
+    expired: Arc<Mutex<Vec<ExpiredApproval>>>,
+}
+
+impl TaskProgress {
+    pub(crate) fn new(
+        task_id: Uuid,
+        event_tx: broadcast::Sender<GatewayEvent>,
+        gateway: SharedGateway,
+    ) -> Self {
+        Self {
+            task_id,
+            event_tx,
+            gateway,
+            expired: Arc::default(),
+        }
+    }
+
+    /// The task this sink reports for.
+    pub fn task_id(&self) -> Uuid {
+        self.task_id
+    }
+
+    /// Broadcast a lifecycle update to connected clients.
+    pub fn send(&self, update: TaskUpdate) {
+        let _ = self.event_tx.send(GatewayEvent::TaskUpdate {
+            task_id: self.task_id,
+            update,
+        });
+    }
+
+    /// Queue an approval request on the dashboard and wait for the decision,
+    /// for as long as the `[safety.approval_timeouts]` policy for `risk_level`
+    /// allows. Denies if the gateway drops the request without a decision.
+    pub async fn request_approval(
+        &self,
+        tool_name: &str,
+        description: &str,
+        risk_level: RiskLevel,
+    ) -> ApprovalDecision {
+        let policy = self.gateway.lock().await.approval_policy(risk_level);
+        self.request_approval_with_policy(tool_name, description, risk_level, policy)
+            .await
+    }
+
+    /// Show a form on the dashboard and wait for its first valid
+    /// submission. Resolves [`FormOutcome::Expired`] once the form's
+    /// `expires_in_secs` pass without one; without an expiry it waits for
+    /// as long as the caller does.
+    pub async fn wait_for_form(&self, spec: FormSpec) -> FormOutcome {
+        let form = PendingForm::new(spec, Some(self.task_id));
+        let (id, expires_at) = (form.id, form.expires_at);
+        let mut rx = self.gateway.lock().await.add_form_waiter(form);
+        let Some(expires_at) = expires_at else {
+            return rx.await.unwrap_or(FormOutcome::Expired);
+        };
+        let window = (expires_at - Utc::now()).to_std().unwrap_or_default();
+        if let Ok(result) = tokio::time::timeout(window, &mut rx).await {
+            return result.unwrap_or(FormOutcome::Expired);
+        }
+        // Expire under the gateway lock so a submission racing the deadline
+        // either lands first and stands, or is told the form expired.
+        if self.gateway.lock().await.expire_form(&id) {
+            FormOutcome::Expired
+        } else {
+            rx.await.unwrap_or(FormOutcome::Expired)
+        }
+    }
+
+    /// Approvals of this task that expired without a decision, oldest first.
+    pub fn expired_approvals(&self) -> Vec<ExpiredApproval> {
+        self.expired
+            .lock()
+            .map(|expired| expired.clone())
+            .unwrap_or_default()
+    }
+
+    async fn request_approval_with_policy(
+        &self,
+        tool_name: &str,
+        description: &str,
+        risk_level: RiskLevel,
+        policy: Option<ResolvedPolicy>,
+    ) -> ApprovalDecision {
+        let id = Uuid::new_v4();
+        let created_at = Utc::now();
+        let mut rx = {
+            let mut gw = self.gateway.lock().await;
+            gw.add_approval_waiter(PendingApproval {
+                id,
+                tool_name: tool_name.to_string(),
+                description: description.to_string(),
+                risk_level: risk_level.to_string(),
+                created_at,
+                expires_at: policy.as_ref().map(|p| {
+                    created_at
+                        + chrono::Duration::from_std(p.timeout).unwrap_or(chrono::Duration::MAX)
+                }),
+                escalated: false,
+                policy: policy.as_ref().map(|p| p.source.clone()),
+            })
+        };
+        let Some(policy) = policy else {
+            return decided(rx.await);
+        };
+        if let Ok(result) = tokio::time::timeout(policy.timeout, &mut rx).await {
+            return decided(result);
+        }
+
+        if let Some((target, window)) = &policy.escalation {
+            let expires_at =
+                Utc::now() + chrono::Duration::from_std(*window).unwrap_or(chrono::Duration::MAX);
+            let escalator = {
+                let mut gw = self.gateway.lock().await;
+                if !gw.escalate_approval(&id, &target.channel, expires_at) {
+                    drop(gw);
+                    return decided(rx.await);
+                }
+                gw.approval_escalator()
+            };
+            let message = escalation_message(
+                id,
+                tool_name,
+                description,
+                &risk_level.to_string(),
+                expires_at,
+            );
+            let channel_reply = async {
+                match &escalator {
+                    Some(escalator) => {
+                        match escalator.escalate(id, target, &message, *window).await {
+                            Ok(Some(approved)) => return approved,
+                            Ok(None) => {}
+                            Err(e) => tracing::warn!(
+                                channel = %target.channel,
+                                "Failed to escalate approval: {}", e
+                            ),
+                        }
+                    }
+                    None => tracing::warn!(
+                        channel = %target.channel,
+                        "No approval escalator set; waiting on the dashboard only"
+                    ),
+                }
+                std::future::pending().await
+            };
+            tokio::select! {
+                result = &mut rx => return decided(result),
+                approved = channel_reply => {
+                    // Resolved like a dashboard decision; whichever came
+                    // first reaches `rx`.
+                    self.gateway.lock().await.resolve_approval(&id, approved);
+                    return decided(rx.await);
+                }
+                _ = tokio::time::sleep(*window) => {}
+            }
+        }
+
+        // Expire under the gateway lock so a decision racing the deadline
+        // either lands first and stands, or finds the approval gone.
+        let expired = self.gateway.lock().await.expire_approval(&id, &policy);
+        match expired {
+            Some(expired) => {
+                tracing::warn!(
+                    tool = tool_name,
+                    policy = %expired.policy,
+                    "Approval expired: {}", expired.resolution
+                );
+                let abort = expired.resolution.aborts();
+                if let Ok(mut list) = self.expired.lock() {
+                    list.push(expired);
+                }
+                ApprovalDecision::TimedOut { abort }
+            }
+            None => decided(rx.await),
+        }
+    }
+}
+
+/// The agent-facing decision for a dashboard or channel answer.
+fn decided(result: Result<bool, oneshot::error::RecvError>) -> ApprovalDecision {
+    if result.unwrap_or(false) {
+        ApprovalDecision::Approve
+    } else {
+        ApprovalDecision::Deny
+    }
+}
+
+/// Agent callback that streams a gateway task's progress to clients and
+/// routes approval requests to the dashboard's approval queue.
+pub struct TaskProgressCallback {
+    progress: TaskProgress,
+    has_streamed: AtomicBool,
+}
+
+impl TaskProgressCallback {
+    pub fn new(progress: TaskProgress) -> Self {
+        Self {
+            progress,
+            has_streamed: AtomicBool::new(false),
+        }
+    }
+}
+
+#[async_trait]
+impl AgentCallback for TaskProgressCallback {
+    async fn on_assistant_message(&self, message: &str) {
+        // Streamed tokens already carried this text.
+        if !self.has_streamed.swap(false, Ordering::SeqCst) {
+            self.progress.send(TaskUpdate::AssistantText {
+                text: message.to_string(),
+            });
+        }
+    }
+
+    async fn on_token(&self, token: &str) {
+        self.has_streamed.store(true, Ordering::SeqCst);
+        self.progress.send(TaskUpdate::AssistantText {
+            text: token.to_string(),
+        });
+    }
+
+    async fn request_approval(&self, action: &ActionRequest) -> ApprovalDecision {
+        self.progress
+            .request_approval(&action.tool_name, &action.description, action.risk_level)
+            .await
+    }
+
+    // Reported from `on_tool_call_started`, which carries the call summary.
+    async fn on_tool_start(&self, _tool_name: &str, _args: &serde_json::Value) {}
+
+    async fn on_tool_call_started(&self, tool_name: &str, summary: &str) {
+        self.progress.send(TaskUpdate::ToolStarted {
+            tool_name: tool_name.to_string(),
+            summary: Some(summary.to_string()),
+        });
+    }
+
+    async fn on_tool_result(&self, tool_name: &str, output: &ToolOutput, duration_ms: u64) {
+        let failed = output
+            .metadata
+            .get("is_error")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        self.progress.send(TaskUpdate::ToolFinished {
+            tool_name: tool_name.to_string(),
+            success: !failed,
+            duration_ms,
+        });
+    }
+
+    async fn on_status_change(&self, status: AgentStatus) {
+        if status == AgentStatus::Planning {
+            self.progress.send(TaskUpdate::Planning);
+        }
+    }
+
+    async fn on_usage_update(&self, _usage: &TokenUsage, _cost: &CostEstimate) {}
+
+    async fn on_decision_explanation(&self, _explanation: &DecisionExplanation) {}
+
+    async fn on_plan_generating(&self, _goal: &str) {
+        self.progress.send(TaskUpdate::Planning);
+    }
+
+    async fn on_cost_preflight(&self, estimate: &PlanEstimate, _confirm: bool) -> CostDecision {
+        self.progress.send(TaskUpdate::CostEstimated {
+            cost_usd: estimate.cost_usd,
+            llm_calls: estimate.llm_calls,
+        });
+        CostDecision::Unattended
+    }
+
+    async fn on_cost_report(&self, report: &CostReport) {
+        self.progress.send(TaskUpdate::CostReported {
+            estimated_usd: report.estimate.cost_usd,
+            actual_usd: report.actual_cost(),
+            accuracy: report.accuracy(),
+            budget_usd: report.budget_usd,
+        });
+    }
+}
+
+/// Result of a cancellation request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelOutcome {
+    /// The task was still queued and has been removed.
+    Dequeued,
+    /// The running task's cancellation token was triggered.
+    Signalled,
+    /// No queued or running task has that ID.
+    NotFound,
+}
+
+/// FIFO queue of submitted tasks with a parallelism limit.
+#[derive(Debug)]
+pub(crate) struct TaskQueue {
+    max_concurrent: usize,
+    max_queued: usize,
+    queued: VecDeque<TaskRequest>,
+    running: HashMap<Uuid, CancellationToken>,
+}
+
+impl TaskQueue {
+    pub(crate) fn new(max_concurrent: usize, max_queued: usize) -> Self {
+        Self {
+            max_concurrent: max_concurrent.max(1),
+            max_queued,
+            queued: VecDeque::new(),
+            running: HashMap::new(),
+        }
+    }
+
+    /// Change the limits. Running tasks above a lowered parallelism limit
+    /// finish normally; queued tasks beyond a lowered queue limit stay queued.
+    pub(crate) fn set_limits(&mut self, max_concurrent: usize, max_queued: usize) {
+        self.max_concurrent = max_concurrent.max(1);
+        self.max_queued = max_queued;
+    }
+
+    /// Add a task; returns its queue position or an error if the queue is full.
+    pub(crate) fn enqueue(&mut self, request: TaskRequest) -> Result<usize, String> {
+        if self.queued.len() >= self.max_queued {
+            return Err(format!(
+                "Task queue is full ({} waiting); try again later",
+                self.queued.len()
+            ));
+        }
+        self.queued.push_back(request);
+        Ok(self.queued.len() - 1)
+    }
+
+    /// Pop the next task if a runner slot is free, marking it running.
+    pub(crate) fn next_ready(&mut self) -> Option<(TaskRequest, CancellationToken)> {
+        if self.running.len() >= self.max_concurrent {
+            return None;
+        }
+        let request = self.queued.pop_front()?;
+        let token = CancellationToken::new();
+        self.running.insert(request.task_id, token.clone());
+        Some((request, token))
+    }
+
+    pub(crate) fn cancel(&mut self, task_id: &Uuid) -> CancelOutcome {
+        if let Some(pos) = self.queued.iter().position(|t| t.task_id == *task_id) {
+            self.queued.remove(pos);
+            return CancelOutcome::Dequeued;
+        }
+        match self.running.get(task_id) {
+            Some(token) => {
+                token.cancel();
+                CancelOutcome::Signalled
+            }
+            None => CancelOutcome::NotFound,
+        }
+    }
+
+    /// Remove every queued task, returning their IDs.
+    pub(crate) fn drain_queued(&mut self) -> Vec<Uuid> {
+        self.queued.drain(..).map(|t| t.task_id).collect()
+    }
+
+    /// Trip the cancellation token of every running task; returns how many.
+    pub(crate) fn cancel_running(&mut self) -> usize {
+        for token in self.running.values() {
+            token.cancel();
+        }
+        self.running.len()
+    }
+
+    pub(crate) fn finish(&mut self, task_id: &Uuid) {
+        self.running.remove(task_id);
+    }
+
+    pub(crate) fn running_count(&self) -> usize {
+        self.running.len()
+    }
+
+    pub(crate) fn queued_count(&self) -> usize {
+        self.queued.len()
+    }
+}
+
+/// Start queued tasks while runner slots are free. Each finished task calls
+/// this again, so the queue drains without a dedicated worker.
+pub fn dispatch_tasks(gateway: SharedGateway) -> BoxFuture<'static, ()> {
+    async move {
+        loop {
+            let (request, cancel, runner, progress) = {
+                let mut gw = gateway.lock().await;
+                let Some(runner) = gw.task_runner() else {
+                    return;
+                };
+                let Some((request, cancel)) = gw.tasks_mut().next_ready() else {
+                    return;
+                };
+                let progress =
+                    TaskProgress::new(request.task_id, gw.event_sender(), gateway.clone());
+                (request, cancel, runner, progress)
+            };
+            progress.send(TaskUpdate::Started);
+            tokio::spawn(run_task(gateway.clone(), runner, request, progress, cancel));
+        }
+    }
+    .boxed()
+}
+
+async fn run_task(
+    gateway: SharedGateway,
+    runner: Arc<dyn TaskRunner>,
+    request: TaskRequest,
+    progress: TaskProgress,
+    cancel: CancellationToken,
+) {
+    let task_id = request.task_id;
+    let result = runner.run(request, progress.clone(), cancel.clone()).await;
+    let skipped = progress.expired_approvals();
+    let (update, success, mut summary) = if cancel.is_cancelled() {
+        (
+            TaskUpdate::Cancelled,
+            false,
+            "Cancelled by client".to_string(),
+        )
+    } else {
+        match result {
+            Ok(result) => {
+                let mut summary = TaskSummary::from(&result);
+                summary.skipped_approvals = skipped.clone();
+                let text = summary.response.clone();
+                (TaskUpdate::Completed { summary }, result.success, text)
+            }
+            Err(error) => (
+                TaskUpdate::Failed {
+                    error: error.clone(),
+                },
+                false,
+                error,
+            ),
+        }
+    };
+    if !skipped.is_empty() {
+        summary.push_str("\n\nNot done, approval timed out:");
+        for expired in &skipped {
+            summary.push_str(&format!("\n- {}", expired.summary_line()));
+        }
+    }
+    {
+        let mut gw = gateway.lock().await;
+        gw.tasks_mut().finish(&task_id);
+        progress.send(update);
+        gw.broadcast(GatewayEvent::TaskCompleted {
+            task_id,
+            success,
+            summary,
+        });
+    }
+    dispatch_tasks(gateway).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approval_timeout::{EscalationTarget, TimeoutFallback};
+    use crate::gateway::{ApprovalEscalator, GatewayConfig, GatewayServer};
+    use crate::safety::AuditEvent;
+    use std::time::Duration;
+
+    fn request(text: &str) -> TaskRequest {
+        TaskRequest {
+            task_id: Uuid::new_v4(),
+            text: text.into(),
+            workspace: None,
+            options: TaskOptions::default(),
+            connection_id: Uuid::new_v4(),
+        }
+    }
+
+    #[test]
+    fn test_queue_respects_parallelism_limit() {
+        let mut queue = TaskQueue::new(1, 8);
+        let first = request("one");
+        let second = request("two");
+        assert_eq!(queue.enqueue(first.clone()).unwrap(), 0);
+        assert_eq!(queue.enqueue(second.clone()).unwrap(), 1);
+
+        let (started, _) = queue.next_ready().unwrap();
+        assert_eq!(started.task_id, first.task_id);
+        assert!(queue.next_ready().is_none());
+
+        queue.finish(&first.task_id);
+        let (started, _) = queue.next_ready().unwrap();
+        assert_eq!(started.task_id, second.task_id);
+    }
+
+    #[test]
+    fn test_queue_full_rejects() {
+        let mut queue = TaskQueue::new(1, 1);
+        queue.enqueue(request("one")).unwrap();
+        assert!(queue.enqueue(request("two")).is_err());
+    }
+
+    #[test]
+    fn test_cancel_queued_and_running() {
+        let mut queue = TaskQueue::new(1, 8);
+        let running = request("running");
+        let waiting = request("waiting");
+        queue.enqueue(running.clone()).unwrap();
+        queue.enqueue(waiting.clone()).unwrap();
+        let (_, token) = queue.next_ready().unwrap();
+
+        assert_eq!(queue.cancel(&waiting.task_id), CancelOutcome::Dequeued);
+        assert_eq!(queue.queued_count(), 0);
+        assert_eq!(queue.cancel(&running.task_id), CancelOutcome::Signalled);
+        assert!(token.is_cancelled());
+        assert_eq!(queue.cancel(&Uuid::new_v4()), CancelOutcome::NotFound);
+    }
+
+    #[test]
+    fn test_set_limits_and_drain() {
+        let mut queue = TaskQueue::new(1, 8);
+        for text in ["a", "b", "c"] {
+            queue.enqueue(request(text)).unwrap();
+        }
+        let (_, first) = queue.next_ready().unwrap();
+        assert!(queue.next_ready().is_none());
+
+        queue.set_limits(2, 8);
+        let (_, second) = queue.next_ready().unwrap();
+        assert_eq!(queue.running_count(), 2);
+
+        assert_eq!(queue.drain_queued().len(), 1);
+        assert_eq!(queue.cancel_running(), 2);
+        assert!(first.is_cancelled() && second.is_cancelled());
+    }
+
+    #[test]
+    fn test_task_update_serialization() {
+        let update = TaskUpdate::ToolFinished {
+            tool_name: "shell_exec".into(),
+            success: true,
+            duration_ms: 12,
+        };
+        let json = serde_json::to_string(&update).unwrap();
+        assert!(json.contains("\"stage\":\"tool_finished\""));
+        let restored: TaskUpdate = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, update);
+    }
+
+    fn progress() -> (SharedGateway, TaskProgress) {
+        let gw: SharedGateway = Arc::new(tokio::sync::Mutex::new(GatewayServer::new(
+            GatewayConfig::default(),
+        )));
+        let event_tx = gw.try_lock().unwrap().event_sender();
+        let progress = TaskProgress::new(Uuid::new_v4(), event_tx, gw.clone());
+        (gw, progress)
+    }
+
+    fn policy(escalation: Option<(EscalationTarget, Duration)>) -> ResolvedPolicy {
+        ResolvedPolicy {
+            source: "risk_levels.destructive".into(),
+            timeout: Duration::from_millis(20),
+            escalation,
+            fallback: TimeoutFallback::DenyAndAbort,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_approval_expires_under_policy() {
+        let (gw, progress) = progress();
+        let decision = progress
+            .request_approval_with_policy(
+                "shell_exec",
+                "Run make deploy",
+                RiskLevel::Destructive,
+                Some(policy(None)),
+            )
+            .await;
+        assert_eq!(decision, ApprovalDecision::TimedOut { abort: true });
+
+        let gw = gw.lock().await;
+        assert!(gw.pending_approvals().is_empty());
+        assert!(gw.audit_entries().iter().any(|e| matches!(
+            &e.event,
+            AuditEvent::ApprovalExpired { tool, policy, escalated: false, .. }
+                if tool == "shell_exec" && policy == "risk_levels.destructive"
+        )));
+        let expired = progress.expired_approvals();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].resolution, TimeoutFallback::DenyAndAbort);
+    }
+
+    fn deploy_form(expires_in_secs: Option<u64>) -> FormSpec {
+        let mut spec: FormSpec = serde_json::from_value(serde_json::json!({
+            "title": "Deploy",
+            "fields": [{"name": "env", "label": "Environment", "field_type": "select",
+                        "required": true, "options": ["staging", "production"]}]
+        }))
+        .unwrap();
+        spec.expires_in_secs = expires_in_secs;
+        spec
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_form_resumes_on_first_valid_submission() {
+        let (gw, progress) = progress();
+        let mut events = gw.lock().await.event_sender().subscribe();
+        let waiter = tokio::spawn({
+            let progress = progress.clone();
+            async move { progress.wait_for_form(deploy_form(None)).await }
+        });
+
+        let form_id = loop {
+            if let GatewayEvent::FormRequest {
+                form_id, task_id, ..
+            } = events.recv().await.unwrap()
+            {
+                assert_eq!(task_id, Some(progress.task_id()));
+                break form_id;
+            }
+        };
+        let submit = |env: &str| {
+            serde_json::json!({ "env": env })
+                .as_object()
+                .unwrap()
+                .clone()
+        };
+        {
+            let mut gw = gw.lock().await;
+            assert!(matches!(
+                gw.submit_form(&form_id, &submit("qa"), None),
+                Err(crate::gateway::FormSubmitError::Invalid(_))
+            ));
+            gw.submit_form(&form_id, &submit("staging"), None).unwrap();
+            assert_eq!(
+                gw.submit_form(&form_id, &submit("production"), None)
+                    .unwrap_err(),
+                crate::gateway::FormSubmitError::AlreadySubmitted
+            );
+        }
+
+        match waiter.await.unwrap() {
+            FormOutcome::Submitted { values, .. } => assert_eq!(values["env"], "staging"),
+            other => panic!("expected a submission, got {:?}", other),
+        }
+        assert!(gw.lock().await.audit_entries().iter().any(|e| matches!(
+            &e.event,
+            AuditEvent::FormSubmitted { title, .. } if title == "Deploy"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_form_times_out_when_expired() {
+        let (gw, progress) = progress();
+        let outcome = progress.wait_for_form(deploy_form(Some(0))).await;
+        assert_eq!(outcome, FormOutcome::Expired);
+
+        let mut gw = gw.lock().await;
+        assert!(gw.pending_forms().is_empty());
+        let form_id = gw.closed_forms()[0].id;
+        let values = serde_json::json!({"env": "staging"})
+            .as_object()
+            .unwrap()
+            .clone();
+        assert_eq!(
+            gw.submit_form(&form_id, &values, None).unwrap_err(),
+            crate::gateway::FormSubmitError::Expired
+        );
+    }
+
+    struct ApprovingEscalator;
+
+    #[async_trait]
+    impl ApprovalEscalator for ApprovingEscalator {
+        async fn escalate(
+            &self,
+            _approval_id: Uuid,
+            _target: &EscalationTarget,
+            message: &str,
+            _window: Duration,
+        ) -> Result<Option<bool>, String> {
+            assert!(message.contains("/approve"));
+            Ok(Some(true))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_escalated_approval_decided_on_channel() {
+        let (gw, progress) = progress();
+        gw.lock()
+            .await
+            .set_approval_escalator(Arc::new(ApprovingEscalator));
+        let mut events = gw.lock().await.event_sender().subscribe();
+        let target = EscalationTarget {
+            channel: "telegram".into(),
+            destination_id: "123".into(),
+        };
+        let decision = progress
+            .request_approval_with_policy(
+                "shell_exec",
+                "Run make deploy",
+                RiskLevel::Destructive,
+                Some(policy(Some((target, Duration::from_secs(60))))),
+            )
+            .await;
+        assert_eq!(decision, ApprovalDecision::Approve);
+        assert!(progress.expired_approvals().is_empty());
+        assert!(gw.lock().await.pending_approvals().is_empty());
+
+        let mut escalated = false;
+        while let Ok(event) = events.try_recv() {
+            escalated |= matches!(event, GatewayEvent::ApprovalEscalated { ref channel, .. }
+                if channel == "telegram");
+        }
+        assert!(escalated);
+    }
+}
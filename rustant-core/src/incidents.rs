@@ -0,0 +1,715 @@
+//! Incident response — runbooks, incident records and postmortem timelines.
+//!
+//! Runbooks are YAML files in the workspace's `.rustant/runbooks/`: named
+//! steps with suggested tools and commands, and optional decision points
+//! that choose the next step. An incident records which runbook steps were
+//! executed, when, with what output and by whom, plus a timeline of every
+//! tool call and alert state change while it is open. Incidents live in
+//! `.rustant/incidents/<id>.json`; at most one is open at a time.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const RUNBOOKS_DIR: &str = "runbooks";
+const INCIDENTS_DIR: &str = "incidents";
+const EXPORTS_DIR: &str = "exports";
+/// Longest tool output kept on a timeline event.
+const MAX_SUMMARY_CHARS: usize = 300;
+
+/// Errors loading runbooks or managing incidents.
+#[derive(Debug, thiserror::Error)]
+pub enum IncidentError {
+    #[error("Failed to access {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+    #[error("Unknown runbook '{name}'")]
+    RunbookNotFound { name: String },
+    #[error("Runbook '{runbook}' has no step '{step}'")]
+    UnknownStep { runbook: String, step: String },
+    #[error("Incident {id} ('{title}') is still open; close it first")]
+    AlreadyOpen { id: String, title: String },
+    #[error("No incident is open")]
+    NoOpenIncident,
+    #[error("Closing an incident requires: {}", fields.join(", "))]
+    MissingCloseFields { fields: Vec<&'static str> },
+}
+
+/// Incident notification and safety settings (`[incidents]` in config).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncidentsConfig {
+    /// Channel notified when an incident starts with `notify: true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify: Option<IncidentNotify>,
+    /// Require approval for every destructive action while an incident is open.
+    #[serde(default = "default_raise_safety")]
+    pub raise_safety: bool,
+}
+
+fn default_raise_safety() -> bool {
+    true
+}
+
+impl Default for IncidentsConfig {
+    fn default() -> Self {
+        Self {
+            notify: None,
+            raise_safety: true,
+        }
+    }
+}
+
+/// Where incident start notifications are sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncidentNotify {
+    /// Channel name as registered in `[channels]` (e.g. "slack").
+    pub channel: String,
+    /// Conversation/chat ID on that channel.
+    #[serde(default)]
+    pub destination_id: String,
+}
+
+/// A guided response procedure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Runbook {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub steps: Vec<RunbookStep>,
+}
+
+/// One named step of a runbook.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunbookStep {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Tools suggested for this step.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    /// Commands suggested for this step.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+    /// Question whose answer picks the next step.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision: Option<DecisionPoint>,
+}
+
+/// A branch in a runbook.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionPoint {
+    pub question: String,
+    pub options: Vec<DecisionOption>,
+}
+
+/// An answer to a decision point and the step it leads to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionOption {
+    pub label: String,
+    /// Step to continue with; the runbook ends here when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+}
+
+impl Runbook {
+    /// Runbooks in `<workspace>/.rustant/runbooks/*.yaml`, sorted by name.
+    pub fn load_all(workspace: &Path) -> Result<Vec<Runbook>, IncidentError> {
+        let dir = workspace.join(".rustant").join(RUNBOOKS_DIR);
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => return Err(IncidentError::Io { path: dir, source }),
+        };
+        let mut runbooks = Vec::new();
+        for path in entries.flatten().map(|e| e.path()) {
+            if !matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("yaml" | "yml")
+            ) {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path).map_err(|source| IncidentError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            let runbook: Runbook =
+                serde_yaml::from_str(&content).map_err(|e| IncidentError::Parse {
+                    path: path.clone(),
+                    message: e.to_string(),
+                })?;
+            runbooks.push(runbook);
+        }
+        runbooks.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(runbooks)
+    }
+
+    /// The workspace runbook called `name`.
+    pub fn find(workspace: &Path, name: &str) -> Result<Runbook, IncidentError> {
+        Self::load_all(workspace)?
+            .into_iter()
+            .find(|r| r.name == name)
+            .ok_or_else(|| IncidentError::RunbookNotFound {
+                name: name.to_string(),
+            })
+    }
+
+    pub fn step(&self, id: &str) -> Option<&RunbookStep> {
+        self.steps.iter().find(|s| s.id == id)
+    }
+}
+
+/// Who carried out a step or tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Executor {
+    /// The agent, without needing approval.
+    Agent,
+    /// The agent, after a human approved the action.
+    HumanApproved,
+    /// A human, outside the agent.
+    Human,
+}
+
+impl Executor {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "agent" => Some(Self::Agent),
+            "human_approved" => Some(Self::HumanApproved),
+            "human" => Some(Self::Human),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Executor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Agent => "agent",
+            Self::HumanApproved => "agent (human-approved)",
+            Self::Human => "human",
+        })
+    }
+}
+
+/// Incident severity, required when closing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IncidentSeverity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl IncidentSeverity {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for IncidentSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        })
+    }
+}
+
+/// A runbook step as it was carried out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepExecution {
+    pub step_id: String,
+    pub title: String,
+    pub executed_at: DateTime<Utc>,
+    pub executed_by: Executor,
+    #[serde(default)]
+    pub output: String,
+    /// Decision option chosen at this step, if it had a decision point.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision: Option<String>,
+}
+
+/// What a timeline event records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineKind {
+    Started,
+    Step,
+    ToolCall,
+    AlertChange,
+    Note,
+    Closed,
+}
+
+impl std::fmt::Display for TimelineKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Started => "started",
+            Self::Step => "step",
+            Self::ToolCall => "tool call",
+            Self::AlertChange => "alert",
+            Self::Note => "note",
+            Self::Closed => "closed",
+        })
+    }
+}
+
+/// One entry of an incident timeline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    pub at: DateTime<Utc>,
+    pub kind: TimelineKind,
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executed_by: Option<Executor>,
+}
+
+/// An incident and everything done about it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncidentRecord {
+    pub id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runbook: Option<String>,
+    pub started_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub steps: Vec<StepExecution>,
+    #[serde(default)]
+    pub timeline: Vec<TimelineEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<IncidentSeverity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_cause: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+}
+
+impl IncidentRecord {
+    pub fn new(title: impl Into<String>, runbook: Option<String>) -> Self {
+        let started_at = Utc::now();
+        let title = title.into();
+        let mut record = Self {
+            id: format!("INC-{}", started_at.format("%Y%m%d-%H%M%S")),
+            title: title.clone(),
+            runbook,
+            started_at,
+            closed_at: None,
+            steps: Vec::new(),
+            timeline: Vec::new(),
+            severity: None,
+            root_cause: None,
+            resolution: None,
+        };
+        record.push_event(TimelineKind::Started, title, None);
+        record
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.closed_at.is_none()
+    }
+
+    fn push_event(&mut self, kind: TimelineKind, summary: String, by: Option<Executor>) {
+        self.timeline.push(TimelineEvent {
+            at: Utc::now(),
+            kind,
+            summary: clip(&summary),
+            executed_by: by,
+        });
+    }
+
+    /// Record that a runbook step was carried out.
+    pub fn record_step(
+        &mut self,
+        step: &RunbookStep,
+        executed_by: Executor,
+        output: impl Into<String>,
+        decision: Option<String>,
+    ) {
+        let output = output.into();
+        let mut summary = format!("{}: {}", step.id, step.title);
+        if let Some(decision) = &decision {
+            summary.push_str(&format!(" → {}", decision));
+        }
+        self.steps.push(StepExecution {
+            step_id: step.id.clone(),
+            title: step.title.clone(),
+            executed_at: Utc::now(),
+            executed_by,
+            output,
+            decision,
+        });
+        self.push_event(TimelineKind::Step, summary, Some(executed_by));
+    }
+
+    /// Record a tool call made while the incident is open.
+    pub fn record_tool_call(&mut self, tool: &str, executed_by: Executor, outcome: &str) {
+        self.push_event(
+            TimelineKind::ToolCall,
+            format!("{}: {}", tool, outcome),
+            Some(executed_by),
+        );
+    }
+
+    /// Record an alert or service health state change.
+    pub fn record_alert(&mut self, summary: impl Into<String>) {
+        self.push_event(TimelineKind::AlertChange, summary.into(), None);
+    }
+
+    pub fn note(&mut self, text: impl Into<String>, by: Executor) {
+        self.push_event(TimelineKind::Note, text.into(), Some(by));
+    }
+
+    /// The runbook step to do next: where the last decision leads, else the
+    /// first step not yet executed.
+    pub fn next_step<'a>(&self, runbook: &'a Runbook) -> Option<&'a RunbookStep> {
+        if let Some(last) = self.steps.last()
+            && let Some(decision) = &last.decision
+            && let Some(step) = runbook.step(&last.step_id)
+            && let Some(point) = &step.decision
+            && let Some(option) = point.options.iter().find(|o| &o.label == decision)
+        {
+            return option.next.as_deref().and_then(|id| runbook.step(id));
+        }
+        runbook
+            .steps
+            .iter()
+            .find(|s| !self.steps.iter().any(|e| e.step_id == s.id))
+    }
+
+    /// Close the incident. Severity and root cause are required; the
+    /// error lists whichever are missing.
+    pub fn close(
+        &mut self,
+        severity: Option<IncidentSeverity>,
+        root_cause: Option<String>,
+        resolution: Option<String>,
+    ) -> Result<(), IncidentError> {
+        let root_cause = root_cause.filter(|r| !r.trim().is_empty());
+        let mut missing = Vec::new();
+        if severity.is_none() {
+            missing.push("severity");
+        }
+        if root_cause.is_none() {
+            missing.push("root_cause");
+        }
+        if !missing.is_empty() {
+            return Err(IncidentError::MissingCloseFields { fields: missing });
+        }
+        self.severity = severity;
+        self.root_cause = root_cause;
+        self.resolution = resolution;
+        self.closed_at = Some(Utc::now());
+        self.push_event(
+            TimelineKind::Closed,
+            format!("Closed with severity {}", self.severity.expect("checked")),
+            None,
+        );
+        Ok(())
+    }
+
+    /// Postmortem-ready markdown: summary, executed steps and timeline.
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Incident {}: {}\n\n", self.id, self.title);
+        let status = if self.is_open() { "open" } else { "closed" };
+        md.push_str(&format!("- **Status:** {}\n", status));
+        md.push_str(&format!(
+            "- **Started:** {}\n",
+            format_time(self.started_at)
+        ));
+        if let Some(closed) = self.closed_at {
+            let minutes = (closed - self.started_at).num_minutes();
+            md.push_str(&format!(
+                "- **Closed:** {} ({} min)\n",
+                format_time(closed),
+                minutes
+            ));
+        }
+        if let Some(severity) = self.severity {
+            md.push_str(&format!("- **Severity:** {}\n", severity));
+        }
+        if let Some(runbook) = &self.runbook {
+            md.push_str(&format!("- **Runbook:** {}\n", runbook));
+        }
+        if let Some(root_cause) = &self.root_cause {
+            md.push_str(&format!("- **Root cause:** {}\n", root_cause));
+        }
+        if let Some(resolution) = &self.resolution {
+            md.push_str(&format!("- **Resolution:** {}\n", resolution));
+        }
+
+        if !self.steps.is_empty() {
+            md.push_str("\n## Runbook Steps\n\n| Time (UTC) | Step | By | Decision | Output |\n|---|---|---|---|---|\n");
+            for step in &self.steps {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    format_time(step.executed_at),
+                    cell(&format!("{}: {}", step.step_id, step.title)),
+                    step.executed_by,
+                    cell(step.decision.as_deref().unwrap_or("")),
+                    cell(&clip(&step.output)),
+                ));
+            }
+        }
+
+        md.push_str("\n## Timeline\n\n| Time (UTC) | Event | By | Details |\n|---|---|---|---|\n");
+        for event in &self.timeline {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                format_time(event.at),
+                event.kind,
+                event.executed_by.map(|e| e.to_string()).unwrap_or_default(),
+                cell(&event.summary),
+            ));
+        }
+        md
+    }
+}
+
+/// Incident records of a workspace.
+pub struct IncidentStore {
+    dir: PathBuf,
+}
+
+impl IncidentStore {
+    pub fn new(workspace: &Path) -> Self {
+        Self {
+            dir: workspace.join(".rustant").join(INCIDENTS_DIR),
+        }
+    }
+
+    fn record_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    pub fn load(&self, id: &str) -> Result<IncidentRecord, IncidentError> {
+        let path = self.record_path(id);
+        let content = std::fs::read_to_string(&path).map_err(|source| IncidentError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        serde_json::from_str(&content).map_err(|e| IncidentError::Parse {
+            path,
+            message: e.to_string(),
+        })
+    }
+
+    /// Write the record atomically (temp file + rename).
+    pub fn save(&self, record: &IncidentRecord) -> Result<(), IncidentError> {
+        let path = self.record_path(&record.id);
+        let json = serde_json::to_string_pretty(record).expect("incident record serializes");
+        write_atomic(&path, &json)
+    }
+
+    /// All incidents, newest first. Unreadable records are skipped.
+    pub fn list(&self) -> Vec<IncidentRecord> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut records: Vec<IncidentRecord> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "json"))
+            .filter_map(|p| std::fs::read_to_string(p).ok())
+            .filter_map(|c| serde_json::from_str(&c).ok())
+            .collect();
+        records.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+        records
+    }
+
+    /// The open incident, if any.
+    pub fn open_incident(&self) -> Option<IncidentRecord> {
+        self.list().into_iter().find(IncidentRecord::is_open)
+    }
+
+    /// Start a new incident; fails while another is open.
+    pub fn start(
+        &self,
+        title: &str,
+        runbook: Option<String>,
+    ) -> Result<IncidentRecord, IncidentError> {
+        if let Some(open) = self.open_incident() {
+            return Err(IncidentError::AlreadyOpen {
+                id: open.id,
+                title: open.title,
+            });
+        }
+        let record = IncidentRecord::new(title, runbook);
+        self.save(&record)?;
+        Ok(record)
+    }
+
+    /// Apply `update` to the open incident and save it.
+    pub fn update_open<T>(
+        &self,
+        update: impl FnOnce(&mut IncidentRecord) -> Result<T, IncidentError>,
+    ) -> Result<(IncidentRecord, T), IncidentError> {
+        let mut record = self.open_incident().ok_or(IncidentError::NoOpenIncident)?;
+        let value = update(&mut record)?;
+        self.save(&record)?;
+        Ok((record, value))
+    }
+
+    /// Write the markdown timeline and JSON record to `exports/`,
+    /// returning both paths.
+    pub fn export(&self, record: &IncidentRecord) -> Result<(PathBuf, PathBuf), IncidentError> {
+        let dir = self.dir.join(EXPORTS_DIR);
+        let markdown = dir.join(format!("{}.md", record.id));
+        let json = dir.join(format!("{}.json", record.id));
+        write_atomic(&markdown, &record.to_markdown())?;
+        write_atomic(
+            &json,
+            &serde_json::to_string_pretty(record).expect("incident record serializes"),
+        )?;
+        Ok((markdown, json))
+    }
+}
+
+/// Add an alert state change to the workspace's open incident, if any.
+pub fn record_alert_change(workspace: &Path, summary: &str) {
+    let store = IncidentStore::new(workspace);
+    if let Err(e) = store.update_open(|record| {
+        record.record_alert(summary);
+        Ok(())
+    }) && !matches!(e, IncidentError::NoOpenIncident)
+    {
+        tracing::warn!(error = %e, "Failed to record alert change on incident");
+    }
+}
+
+fn write_atomic(path: &Path, content: &str) -> Result<(), IncidentError> {
+    let io_err = |source| IncidentError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, content).map_err(io_err)?;
+    std::fs::rename(&tmp, path).map_err(io_err)
+}
+
+fn format_time(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn clip(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Make `text` safe inside a markdown table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUNBOOK: &str = r#"
+name: db-outage
+description: Primary database unreachable
+steps:
+  - id: check
+    title: Check database health
+    tools: [system_monitor]
+    decision:
+      question: Is the primary reachable?
+      options:
+        - label: "yes"
+          next: app
+        - label: "no"
+          next: failover
+  - id: failover
+    title: Fail over to the replica
+    commands: ["pg_ctl promote"]
+  - id: app
+    title: Restart the application
+"#;
+
+    fn workspace_with_runbook() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let runbooks = dir.path().join(".rustant/runbooks");
+        std::fs::create_dir_all(&runbooks).unwrap();
+        std::fs::write(runbooks.join("db.yaml"), RUNBOOK).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_runbook_decision_picks_next_step() {
+        let dir = workspace_with_runbook();
+        let runbook = Runbook::find(dir.path(), "db-outage").unwrap();
+        assert_eq!(runbook.steps[1].commands, vec!["pg_ctl promote"]);
+
+        let mut record = IncidentRecord::new("DB down", Some(runbook.name.clone()));
+        assert_eq!(record.next_step(&runbook).unwrap().id, "check");
+        let check = runbook.step("check").unwrap();
+        record.record_step(check, Executor::Agent, "timeout", Some("no".into()));
+        assert_eq!(record.next_step(&runbook).unwrap().id, "failover");
+        assert!(matches!(
+            Runbook::find(dir.path(), "missing"),
+            Err(IncidentError::RunbookNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_close_requires_severity_and_root_cause() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IncidentStore::new(dir.path());
+        store.start("API errors", None).unwrap();
+        assert!(matches!(
+            store.start("Another", None),
+            Err(IncidentError::AlreadyOpen { .. })
+        ));
+        record_alert_change(dir.path(), "api #1: healthy → down");
+
+        let err = store
+            .update_open(|r| r.close(None, Some(" ".into()), None))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Closing an incident requires: severity, root_cause"
+        );
+        let (record, ()) = store
+            .update_open(|r| {
+                r.record_tool_call("shell_exec", Executor::HumanApproved, "ok");
+                r.close(
+                    Some(IncidentSeverity::High),
+                    Some("expired TLS cert".into()),
+                    None,
+                )
+            })
+            .unwrap();
+        assert!(store.open_incident().is_none());
+
+        let (markdown, json) = store.export(&record).unwrap();
+        let md = std::fs::read_to_string(markdown).unwrap();
+        assert!(md.contains("- **Severity:** high"));
+        assert!(md.contains("- **Root cause:** expired TLS cert"));
+        assert!(md.contains("| alert |  | api #1: healthy → down |"));
+        assert!(md.contains("| tool call | agent (human-approved) | shell_exec: ok |"));
+        let exported: IncidentRecord =
+            serde_json::from_str(&std::fs::read_to_string(json).unwrap()).unwrap();
+        assert_eq!(exported, record);
+    }
+}
@@ -3,12 +3,28 @@
 //! Background workspace indexer that walks the project directory, respects
 //! `.gitignore`, extracts file paths, function signatures, and content summaries,
 //! then indexes them into the `HybridSearchEngine` for semantic codebase search.
+//!
+//! [`WorkspaceIgnore`] adds `.rustantignore` files (gitignore syntax, at the
+//! root or in any subdirectory) and the `[index] ignore` patterns on top of
+//! `.gitignore`; file search and code intelligence walk with the same rules.
+//! Per-directory budgets keep one huge folder from dominating the index, and
+//! [`survey_workspace`] reports what a pass leaves out and why.
+//!
+//! The indexer also keeps the list of indexed files and a symbol table of
+//! definition sites in memory, so structured searches (by symbol kind, path or
+//! language) can filter the index instead of walking the workspace again.
 
 use crate::project_detect::{ProjectInfo, detect_project};
-use crate::search::{HybridSearchEngine, SearchConfig, SearchResult};
+use crate::search::{
+    FactMetadata, HybridSearchEngine, SearchConfig, SearchPage, SearchQuery, SearchResult,
+};
 use ignore::WalkBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::{Path, PathBuf};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Maximum file size to index (256 KB).
 const MAX_FILE_SIZE: u64 = 256 * 1024;
@@ -16,6 +32,12 @@ const MAX_FILE_SIZE: u64 = 256 * 1024;
 /// Maximum number of files to index.
 const MAX_FILES: usize = 5000;
 
+/// Deepest directory level the indexer walks.
+const MAX_DEPTH: usize = 10;
+
+/// Name of the ignore files read on top of `.gitignore`, in gitignore syntax.
+pub const RUSTANT_IGNORE_FILE: &str = ".rustantignore";
+
 /// File extensions considered indexable source code.
 const SOURCE_EXTENSIONS: &[&str] = &[
     "rs",
@@ -67,15 +89,311 @@ pub struct IndexStats {
     pub entries_indexed: usize,
     /// Number of files skipped (too large, binary, etc.).
     pub files_skipped: usize,
+    /// What was skipped, and why.
+    pub skipped: SkipReport,
     /// Detected project info.
     pub project_info: Option<ProjectInfo>,
 }
 
+/// Indexing settings (`[index]`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndexConfig {
+    /// Paths ignored in every workspace, in gitignore syntax, on top of
+    /// `.gitignore` and `.rustantignore`.
+    pub ignore: Vec<String>,
+    /// Files indexed per directory at most; 0 for no limit.
+    pub max_files_per_dir: usize,
+    /// Bytes indexed per directory at most; 0 for no limit.
+    pub max_bytes_per_dir: u64,
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        Self {
+            ignore: vec![
+                "node_modules/".into(),
+                "*.min.js".into(),
+                "*.min.css".into(),
+                "*.map".into(),
+            ],
+            max_files_per_dir: 500,
+            max_bytes_per_dir: 8 * 1024 * 1024,
+        }
+    }
+}
+
+/// What a walk of the workspace left out of the index.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SkipReport {
+    /// `.rustantignore` files in effect, workspace-relative.
+    pub ignore_files: Vec<String>,
+    /// The configured `[index] ignore` patterns.
+    pub ignore_patterns: Vec<String>,
+    /// Directories that went over their indexing budget.
+    pub truncated: Vec<DirTruncation>,
+    /// Files over the size limit, workspace-relative.
+    pub too_large: Vec<String>,
+    /// Number of files of a type the indexer does not read.
+    pub unsupported: usize,
+    /// Whether the walk stopped at the `max_files` limit.
+    pub reached_file_limit: bool,
+}
+
+/// A directory whose files were only partly indexed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DirTruncation {
+    /// Workspace-relative directory; empty for the workspace root.
+    pub dir: String,
+    pub indexed: usize,
+    pub skipped: usize,
+    pub bytes_skipped: u64,
+}
+
+impl fmt::Display for DirTruncation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dir = if self.dir.is_empty() { "." } else { &self.dir };
+        write!(
+            f,
+            "{}/: indexed {} of {} files ({} skipped, {} KB)",
+            dir,
+            self.indexed,
+            self.indexed + self.skipped,
+            self.skipped,
+            self.bytes_skipped / 1024
+        )
+    }
+}
+
+/// Ignore rules shared by everything that walks the workspace:
+/// `.rustantignore` files at the root and in subdirectories, and the
+/// configured `[index] ignore` patterns. Walkers apply `.gitignore`
+/// themselves.
+#[derive(Debug, Clone)]
+pub struct WorkspaceIgnore {
+    workspace: PathBuf,
+    patterns: Gitignore,
+    pattern_list: Vec<String>,
+}
+
+/// The rule that ignores a path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoredBy {
+    /// The ignore file, workspace-relative, or `[index] ignore`.
+    pub source: String,
+    pub pattern: String,
+}
+
+impl fmt::Display for IgnoredBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' in {}", self.pattern, self.source)
+    }
+}
+
+impl WorkspaceIgnore {
+    /// Rules for `workspace` with `patterns` in gitignore syntax. Invalid
+    /// patterns are logged and left out.
+    pub fn new(workspace: &Path, patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new(workspace);
+        let mut pattern_list = Vec::new();
+        for pattern in patterns {
+            match builder.add_line(None, pattern) {
+                Ok(_) => pattern_list.push(pattern.clone()),
+                Err(e) => warn!(pattern = %pattern, error = %e, "Invalid [index] ignore pattern"),
+            }
+        }
+        let patterns = builder.build().unwrap_or_else(|e| {
+            warn!(error = %e, "Invalid [index] ignore patterns");
+            Gitignore::empty()
+        });
+        Self {
+            workspace: workspace.to_path_buf(),
+            patterns,
+            pattern_list,
+        }
+    }
+
+    /// Rules for `workspace` with the `[index] ignore` patterns from its
+    /// configuration.
+    pub fn load(workspace: &Path) -> Self {
+        let config = crate::config::load_config(Some(workspace), None).unwrap_or_default();
+        Self::new(workspace, &config.index.ignore)
+    }
+
+    /// The configured patterns in effect.
+    pub fn patterns(&self) -> &[String] {
+        &self.pattern_list
+    }
+
+    /// Make `builder` skip what these rules ignore.
+    pub fn apply<'a>(&self, builder: &'a mut WalkBuilder) -> &'a mut WalkBuilder {
+        let workspace = self.workspace.clone();
+        let patterns = self.patterns.clone();
+        builder
+            .add_custom_ignore_filename(RUSTANT_IGNORE_FILE)
+            .filter_entry(move |entry| {
+                let path = entry.path();
+                if path == workspace || !path.starts_with(&workspace) {
+                    return true;
+                }
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                !patterns
+                    .matched_path_or_any_parents(path, is_dir)
+                    .is_ignore()
+            })
+    }
+
+    /// The rule that ignores `path`, checking the configured patterns, then
+    /// `.rustantignore` and then `.gitignore` files from the deepest
+    /// directory up. `None` when nothing ignores it.
+    pub fn explain(&self, path: &Path) -> Option<IgnoredBy> {
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.workspace.join(path)
+        };
+        if path == self.workspace || !path.starts_with(&self.workspace) {
+            return None;
+        }
+        let is_dir = path.is_dir();
+        if let ignore::Match::Ignore(glob) =
+            self.patterns.matched_path_or_any_parents(&path, is_dir)
+        {
+            return Some(IgnoredBy {
+                source: "[index] ignore".to_string(),
+                pattern: glob.original().to_string(),
+            });
+        }
+        let in_git = self.workspace.ancestors().any(|a| a.join(".git").exists());
+        let files: &[&str] = if in_git {
+            &[RUSTANT_IGNORE_FILE, ".gitignore"]
+        } else {
+            &[RUSTANT_IGNORE_FILE]
+        };
+        for name in files {
+            let dirs = path
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| dir.starts_with(&self.workspace));
+            for dir in dirs {
+                let file = dir.join(name);
+                if !file.is_file() {
+                    continue;
+                }
+                let (rules, _) = Gitignore::new(&file);
+                match rules.matched_path_or_any_parents(&path, is_dir) {
+                    ignore::Match::Ignore(glob) => {
+                        return Some(IgnoredBy {
+                            source: self.relative(&file),
+                            pattern: glob.original().to_string(),
+                        });
+                    }
+                    // A deeper file, or `.rustantignore` over `.gitignore`,
+                    // re-including the path wins.
+                    ignore::Match::Whitelist(_) => return None,
+                    ignore::Match::None => {}
+                }
+            }
+        }
+        None
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.workspace)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+/// Kind of symbol definition recorded in the symbol table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Function,
+    Struct,
+    Enum,
+    Trait,
+    Class,
+    Interface,
+    Impl,
+    Module,
+    Type,
+}
+
+impl SymbolKind {
+    /// Every kind, in the order shown to users.
+    pub const ALL: [SymbolKind; 9] = [
+        SymbolKind::Function,
+        SymbolKind::Struct,
+        SymbolKind::Enum,
+        SymbolKind::Trait,
+        SymbolKind::Class,
+        SymbolKind::Interface,
+        SymbolKind::Impl,
+        SymbolKind::Module,
+        SymbolKind::Type,
+    ];
+
+    /// Parse a kind name, accepting common aliases (`fn`, `method`, `mod`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "function" | "fn" | "func" | "method" | "def" => Some(Self::Function),
+            "struct" => Some(Self::Struct),
+            "enum" => Some(Self::Enum),
+            "trait" => Some(Self::Trait),
+            "class" => Some(Self::Class),
+            "interface" => Some(Self::Interface),
+            "impl" => Some(Self::Impl),
+            "module" | "mod" => Some(Self::Module),
+            "type" | "typedef" => Some(Self::Type),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Function => "function",
+            Self::Struct => "struct",
+            Self::Enum => "enum",
+            Self::Trait => "trait",
+            Self::Class => "class",
+            Self::Interface => "interface",
+            Self::Impl => "impl",
+            Self::Module => "module",
+            Self::Type => "type",
+        }
+    }
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A symbol definition site found while indexing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    /// Workspace-relative path of the defining file.
+    pub path: String,
+    /// 1-based line of the definition.
+    pub line: usize,
+    pub kind: SymbolKind,
+    pub name: String,
+    /// The definition line, without a trailing `{` or `:`.
+    pub signature: String,
+}
+
 /// The project context indexer.
 pub struct ProjectIndexer {
     workspace: PathBuf,
     engine: HybridSearchEngine,
     config: IndexerConfig,
+    /// Workspace-relative paths of indexed files, in walk order.
+    files: Vec<String>,
+    /// Definition sites, grouped by file and ordered by line within a file.
+    symbols: Vec<Symbol>,
 }
 
 /// Configuration for the indexer.
@@ -89,19 +407,212 @@ pub struct IndexerConfig {
     pub index_content: bool,
     /// Whether to extract and index function signatures.
     pub index_signatures: bool,
+    /// Paths ignored on top of `.gitignore` and `.rustantignore`, in
+    /// gitignore syntax.
+    pub ignore: Vec<String>,
+    /// Files indexed per directory at most; 0 for no limit.
+    pub max_files_per_dir: usize,
+    /// Bytes indexed per directory at most; 0 for no limit.
+    pub max_bytes_per_dir: u64,
 }
 
 impl Default for IndexerConfig {
     fn default() -> Self {
+        Self::from(&IndexConfig::default())
+    }
+}
+
+impl From<&IndexConfig> for IndexerConfig {
+    fn from(config: &IndexConfig) -> Self {
         Self {
             max_file_size: MAX_FILE_SIZE,
             max_files: MAX_FILES,
             index_content: true,
             index_signatures: true,
+            ignore: config.ignore.clone(),
+            max_files_per_dir: config.max_files_per_dir,
+            max_bytes_per_dir: config.max_bytes_per_dir,
         }
     }
 }
 
+/// Where a path stands with respect to the index.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathStatus {
+    Indexed,
+    /// Nothing exists at the path.
+    Absent,
+    Ignored(IgnoredBy),
+    /// The path or one of its directories is hidden.
+    Hidden,
+    TooLarge(u64),
+    /// A directory, or a file type the indexer does not read.
+    Unsupported,
+    /// Left out because its directory went over its budget.
+    Truncated(DirTruncation),
+    /// Left out because the walk stopped at the `max_files` limit.
+    OverFileLimit,
+    /// Deeper than the indexer walks.
+    TooDeep,
+}
+
+impl fmt::Display for PathStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Indexed => write!(f, "indexed"),
+            Self::Absent => write!(f, "absent: no such file in the workspace"),
+            Self::Ignored(by) => write!(f, "ignored by {}", by),
+            Self::Hidden => write!(f, "skipped: hidden path"),
+            Self::TooLarge(size) => {
+                write!(f, "skipped: {} KB is over the file size limit", size / 1024)
+            }
+            Self::Unsupported => write!(f, "skipped: not a file type the indexer reads"),
+            Self::Truncated(dir) => write!(f, "truncated: {}", dir),
+            Self::OverFileLimit => write!(f, "skipped: the index reached its file limit"),
+            Self::TooDeep => write!(f, "skipped: deeper than {} levels", MAX_DEPTH),
+        }
+    }
+}
+
+/// Walk `workspace` as an indexing pass would, without indexing anything.
+/// Returns the workspace-relative files that would be indexed, in walk order,
+/// and what was left out.
+pub fn survey_workspace(workspace: &Path, config: &IndexerConfig) -> (Vec<String>, SkipReport) {
+    let ignore = WorkspaceIgnore::new(workspace, &config.ignore);
+    let mut builder = WalkBuilder::new(workspace);
+    builder
+        .hidden(true) // respect hidden files
+        .git_ignore(true) // respect .gitignore
+        .git_global(true) // respect global gitignore
+        .git_exclude(true) // respect .git/info/exclude
+        .max_depth(Some(MAX_DEPTH))
+        .sort_by_file_name(|a, b| a.cmp(b));
+    ignore.apply(&mut builder);
+
+    let relative = |path: &Path| {
+        path.strip_prefix(workspace)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    };
+    let mut report = SkipReport {
+        ignore_patterns: ignore.patterns().to_vec(),
+        ..Default::default()
+    };
+    // Per directory: files and bytes indexed, files and bytes skipped.
+    let mut budgets: BTreeMap<String, (usize, u64, usize, u64)> = BTreeMap::new();
+    let mut files = Vec::new();
+
+    for entry in builder.build().flatten() {
+        let path = entry.path();
+        if entry.file_type().is_some_and(|t| t.is_dir()) {
+            if path.join(RUSTANT_IGNORE_FILE).is_file() {
+                report
+                    .ignore_files
+                    .push(relative(&path.join(RUSTANT_IGNORE_FILE)));
+            }
+            continue;
+        }
+        if !path.is_file() {
+            continue;
+        }
+        if files.len() >= config.max_files {
+            debug!("Reached max files limit ({})", config.max_files);
+            report.reached_file_limit = true;
+            break;
+        }
+        let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+        if size > config.max_file_size {
+            report.too_large.push(relative(path));
+            continue;
+        }
+        if !is_indexable(path) {
+            report.unsupported += 1;
+            continue;
+        }
+
+        let dir = path.parent().map(relative).unwrap_or_default();
+        let budget = budgets.entry(dir).or_default();
+        let over_files = config.max_files_per_dir > 0 && budget.0 >= config.max_files_per_dir;
+        let over_bytes = config.max_bytes_per_dir > 0 && budget.1 + size > config.max_bytes_per_dir;
+        if over_files || over_bytes {
+            budget.2 += 1;
+            budget.3 += size;
+            continue;
+        }
+        budget.0 += 1;
+        budget.1 += size;
+        files.push(relative(path));
+    }
+
+    report.truncated = budgets
+        .into_iter()
+        .filter(|(_, budget)| budget.2 > 0)
+        .map(
+            |(dir, (indexed, _, skipped, bytes_skipped))| DirTruncation {
+                dir,
+                indexed,
+                skipped,
+                bytes_skipped,
+            },
+        )
+        .collect();
+    (files, report)
+}
+
+/// Why `path` is or is not in the index of `workspace`.
+pub fn explain_path(workspace: &Path, config: &IndexerConfig, path: &Path) -> PathStatus {
+    let full = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        workspace.join(path)
+    };
+    if !full.exists() || !full.starts_with(workspace) {
+        return PathStatus::Absent;
+    }
+    if let Some(by) = WorkspaceIgnore::new(workspace, &config.ignore).explain(&full) {
+        return PathStatus::Ignored(by);
+    }
+    let rel = full.strip_prefix(workspace).unwrap_or(&full);
+    if rel
+        .components()
+        .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+    {
+        return PathStatus::Hidden;
+    }
+    if rel.components().count() > MAX_DEPTH {
+        return PathStatus::TooDeep;
+    }
+    if !full.is_file() || !is_indexable(&full) {
+        return PathStatus::Unsupported;
+    }
+    let size = full.metadata().map(|m| m.len()).unwrap_or(0);
+    if size > config.max_file_size {
+        return PathStatus::TooLarge(size);
+    }
+    let (files, report) = survey_workspace(workspace, config);
+    if files.iter().any(|f| Path::new(f) == rel) {
+        return PathStatus::Indexed;
+    }
+    let dir = rel
+        .parent()
+        .map(|d| d.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if let Some(truncation) = report.truncated.into_iter().find(|t| t.dir == dir) {
+        return PathStatus::Truncated(truncation);
+    }
+    // Not ignored by our rules, so a `.gitignore` the walker honours
+    // (global or `.git/info/exclude`) or the file limit left it out.
+    if report.reached_file_limit {
+        PathStatus::OverFileLimit
+    } else {
+        PathStatus::Ignored(IgnoredBy {
+            source: "git exclude rules".to_string(),
+            pattern: "(global gitignore or .git/info/exclude)".to_string(),
+        })
+    }
+}
+
 impl ProjectIndexer {
     /// Create a new indexer for the given workspace.
     pub fn new(
@@ -113,6 +624,8 @@ impl ProjectIndexer {
             workspace,
             engine,
             config: IndexerConfig::default(),
+            files: Vec::new(),
+            symbols: Vec::new(),
         })
     }
 
@@ -127,6 +640,8 @@ impl ProjectIndexer {
             workspace,
             engine,
             config,
+            files: Vec::new(),
+            symbols: Vec::new(),
         })
     }
 
@@ -143,88 +658,96 @@ impl ProjectIndexer {
         let structure = self.build_structure_summary(&project_info);
         let _ = self.engine.index_fact("__project_structure__", &structure);
 
+        self.files.clear();
+        self.symbols.clear();
         let mut files_indexed = 0;
         let mut entries_indexed = 1; // structure summary counts as 1
-        let mut files_skipped = 0;
-
-        // Walk the workspace respecting .gitignore
-        let walker = WalkBuilder::new(&self.workspace)
-            .hidden(true) // respect hidden files
-            .git_ignore(true) // respect .gitignore
-            .git_global(true) // respect global gitignore
-            .git_exclude(true) // respect .git/info/exclude
-            .max_depth(Some(10))
-            .build();
-
-        for entry in walker.flatten() {
-            if files_indexed >= self.config.max_files {
-                debug!("Reached max files limit ({})", self.config.max_files);
-                break;
-            }
-
-            let path = entry.path();
-
-            // Skip directories and non-files
-            if !path.is_file() {
-                continue;
-            }
 
-            // Skip files that are too large
-            if let Ok(meta) = path.metadata()
-                && meta.len() > self.config.max_file_size
-            {
-                files_skipped += 1;
-                continue;
-            }
-
-            // Check file extension
-            if !is_indexable(path) {
-                files_skipped += 1;
-                continue;
+        let (files, skipped) = survey_workspace(&self.workspace, &self.config);
+        for truncation in &skipped.truncated {
+            let notice = format!(
+                "index truncated by the per-directory budget: {}",
+                truncation
+            );
+            let fact_id = format!("truncated:{}", truncation.dir);
+            if self.engine.index_fact(&fact_id, &notice).is_ok() {
+                entries_indexed += 1;
             }
+        }
 
-            // Get relative path
-            let rel_path = path
-                .strip_prefix(&self.workspace)
-                .unwrap_or(path)
-                .to_string_lossy()
-                .to_string();
+        for rel_path in files {
+            let path = self.workspace.join(&rel_path);
+            let metadata = FactMetadata {
+                path: Some(rel_path.clone()),
+                language: language_for_path(&rel_path).map(str::to_string),
+                kind: None,
+                modified: std::fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .map(chrono::DateTime::<chrono::Utc>::from),
+            };
 
             // Index the file path as an entry
             let path_entry = format!("file: {}", rel_path);
             let fact_id = format!("file:{}", rel_path);
-            if self.engine.index_fact(&fact_id, &path_entry).is_ok() {
+            if self
+                .engine
+                .index_fact_with(&fact_id, &path_entry, metadata.clone())
+                .is_ok()
+            {
                 entries_indexed += 1;
             }
 
             // Optionally index file content
             if self.config.index_content
-                && let Ok(content) = std::fs::read_to_string(path)
+                && let Ok(content) = std::fs::read_to_string(&path)
             {
                 // Index a content summary (first N lines + function signatures)
                 let summary = self.summarize_file(&rel_path, &content);
                 if !summary.is_empty() {
                     let content_id = format!("content:{}", rel_path);
-                    if self.engine.index_fact(&content_id, &summary).is_ok() {
+                    if self
+                        .engine
+                        .index_fact_with(&content_id, &summary, metadata.clone())
+                        .is_ok()
+                    {
                         entries_indexed += 1;
                     }
                 }
 
                 // Extract and index function signatures
                 if self.config.index_signatures {
+                    let symbols = extract_symbols(&content, &rel_path);
                     let signatures = extract_signatures(&content, &rel_path);
                     for (i, sig) in signatures.iter().enumerate() {
                         let sig_id = format!("sig:{}:{}", rel_path, i);
-                        if self.engine.index_fact(&sig_id, sig).is_ok() {
+                        // Signature entries read "path:line signature".
+                        let line = sig[rel_path.len() + 1..]
+                            .split_once(' ')
+                            .and_then(|(line, _)| line.parse::<usize>().ok());
+                        let kind = symbols
+                            .iter()
+                            .find(|s| Some(s.line) == line)
+                            .map(|s| s.kind.as_str().to_string());
+                        let metadata = FactMetadata {
+                            kind,
+                            ..metadata.clone()
+                        };
+                        if self.engine.index_fact_with(&sig_id, sig, metadata).is_ok() {
                             entries_indexed += 1;
                         }
                     }
+                    self.symbols.extend(symbols);
                 }
             }
 
+            self.files.push(rel_path);
             files_indexed += 1;
         }
 
+        let files_skipped = skipped.too_large.len()
+            + skipped.unsupported
+            + skipped.truncated.iter().map(|t| t.skipped).sum::<usize>();
         info!(
             "Indexing complete: {} files indexed, {} entries, {} skipped",
             files_indexed, entries_indexed, files_skipped
@@ -234,6 +757,7 @@ impl ProjectIndexer {
             files_indexed,
             entries_indexed,
             files_skipped,
+            skipped,
             project_info: Some(project_info),
         }
     }
@@ -243,6 +767,36 @@ impl ProjectIndexer {
         self.engine.search(query)
     }
 
+    /// Search the indexed codebase with phrases, filters and paging. Every
+    /// entry carries its file's path, language and modification time, and
+    /// signature entries their symbol kind.
+    pub fn search_query(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<SearchPage, crate::search::SearchError> {
+        self.engine.search_query(query)
+    }
+
+    /// Workspace-relative paths of the files indexed by the last pass.
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    /// Symbol definitions found by the last pass, grouped by file.
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// The innermost symbol defined at or before `line` in `path`.
+    ///
+    /// Definitions are matched by position only, so a line after the end of
+    /// a function still reports that function until the next definition.
+    pub fn enclosing_symbol(&self, path: &str, line: usize) -> Option<&Symbol> {
+        self.symbols
+            .iter()
+            .rfind(|s| s.path == path && s.line <= line)
+    }
+
     /// Get the number of indexed entries.
     pub fn indexed_count(&self) -> usize {
         self.engine.indexed_count()
@@ -346,33 +900,163 @@ fn is_indexable(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// Language name for a path, from its extension.
+pub fn language_for_path(path: &str) -> Option<&'static str> {
+    let ext = Path::new(path).extension()?.to_str()?.to_lowercase();
+    let language = match ext.as_str() {
+        "rs" => "rust",
+        "py" => "python",
+        "js" | "jsx" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "go" => "go",
+        "java" => "java",
+        "kt" => "kotlin",
+        "scala" => "scala",
+        "rb" => "ruby",
+        "c" | "h" => "c",
+        "cpp" | "cc" | "hpp" => "cpp",
+        "cs" => "csharp",
+        "swift" => "swift",
+        "lua" => "lua",
+        "sh" | "bash" | "zsh" => "shell",
+        "md" => "markdown",
+        "toml" => "toml",
+        "yaml" | "yml" => "yaml",
+        "json" => "json",
+        "sql" => "sql",
+        "html" => "html",
+        "css" | "scss" => "css",
+        _ => return None,
+    };
+    Some(language)
+}
+
 /// Extract function/method/class signatures from source code.
 fn extract_signatures(content: &str, path: &str) -> Vec<String> {
-    let mut signatures = Vec::new();
-    let ext = Path::new(path)
+    let ext = file_extension(path);
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            signature_for_line(ext, line.trim()).map(|sig| format!("{}:{} {}", path, i + 1, sig))
+        })
+        .collect()
+}
+
+/// Extract the definition sites whose kind and name can be recognised.
+///
+/// `path` picks the language by extension and is copied into each symbol.
+pub fn extract_symbols(content: &str, path: &str) -> Vec<Symbol> {
+    let ext = file_extension(path);
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let signature = signature_for_line(ext, line.trim())?;
+            let (kind, name) = classify_signature(&signature)?;
+            Some(Symbol {
+                path: path.to_string(),
+                line: i + 1,
+                kind,
+                name,
+                signature,
+            })
+        })
+        .collect()
+}
+
+fn file_extension(path: &str) -> &str {
+    Path::new(path)
         .extension()
         .and_then(|e| e.to_str())
-        .unwrap_or("");
-
-    for (i, line) in content.lines().enumerate() {
-        let trimmed = line.trim();
-        let sig = match ext {
-            "rs" => extract_rust_signature(trimmed),
-            "py" => extract_python_signature(trimmed),
-            "js" | "jsx" | "ts" | "tsx" => extract_js_signature(trimmed),
-            "go" => extract_go_signature(trimmed),
-            "java" | "kt" | "scala" => extract_java_signature(trimmed),
-            "rb" => extract_ruby_signature(trimmed),
-            "c" | "cpp" | "cc" | "h" | "hpp" => extract_c_signature(trimmed),
-            _ => None,
-        };
+        .unwrap_or("")
+}
+
+fn signature_for_line(ext: &str, trimmed: &str) -> Option<String> {
+    match ext {
+        "rs" => extract_rust_signature(trimmed),
+        "py" => extract_python_signature(trimmed),
+        "js" | "jsx" | "ts" | "tsx" => extract_js_signature(trimmed),
+        "go" => extract_go_signature(trimmed),
+        "java" | "kt" | "scala" => extract_java_signature(trimmed),
+        "rb" => extract_ruby_signature(trimmed),
+        "c" | "cpp" | "cc" | "h" | "hpp" => extract_c_signature(trimmed),
+        _ => None,
+    }
+}
 
-        if let Some(sig_text) = sig {
-            signatures.push(format!("{}:{} {}", path, i + 1, sig_text));
+/// Work out the kind and name of the symbol a signature line defines.
+///
+/// The first definition keyword decides the kind and the identifier after
+/// it is the name. Lines without a keyword (Java methods, C functions,
+/// JavaScript arrow functions) are functions named by the identifier before
+/// the first `(` or `=`.
+fn classify_signature(signature: &str) -> Option<(SymbolKind, String)> {
+    let tokens: Vec<&str> = signature.split_whitespace().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        let keyword = token.split('<').next().unwrap_or(token);
+        let kind = match keyword {
+            "fn" | "def" | "func" | "function" | "function*" => SymbolKind::Function,
+            "struct" => SymbolKind::Struct,
+            "enum" => SymbolKind::Enum,
+            "trait" => SymbolKind::Trait,
+            "class" => SymbolKind::Class,
+            "interface" => SymbolKind::Interface,
+            "impl" => SymbolKind::Impl,
+            "mod" | "module" => SymbolKind::Module,
+            "type" | "typedef" => SymbolKind::Type,
+            _ => continue,
+        };
+        let mut rest = &tokens[i + 1..];
+        if kind == SymbolKind::Impl {
+            // `impl Display for Config` is about `Config`.
+            if let Some(pos) = rest.iter().position(|t| *t == "for") {
+                rest = &rest[pos + 1..];
+            }
         }
+        if kind == SymbolKind::Function && rest.first().is_some_and(|t| t.starts_with('(')) {
+            // Go method receivers: `func (s *Server) Start()`.
+            let close = rest.iter().position(|t| t.contains(')'))?;
+            rest = &rest[close + 1..];
+        }
+        let name = identifier(rest.first()?)?;
+        // Go declares structs and interfaces as `type Name struct`.
+        let kind = match (kind, rest.get(1).map(|t| t.trim_end_matches('{'))) {
+            (SymbolKind::Type, Some("struct")) => SymbolKind::Struct,
+            (SymbolKind::Type, Some("interface")) => SymbolKind::Interface,
+            (kind, _) => kind,
+        };
+        return Some((kind, name));
+    }
+
+    // Keyword-less definitions need a type or binding before the name, which
+    // rules out calls (`foo.then(...)`) and control flow (`if (...)`).
+    let head: Vec<&str> = signature
+        .split(['(', '='])
+        .next()?
+        .split_whitespace()
+        .collect();
+    let name = identifier(head.last()?)?;
+    if head.len() < 2 || head.iter().any(|t| NOT_DEFINITIONS.contains(t)) {
+        return None;
     }
+    Some((SymbolKind::Function, name))
+}
+
+/// Words that mark a signature-like line as a statement, not a definition.
+const NOT_DEFINITIONS: &[&str] = &[
+    "if", "else", "for", "while", "switch", "case", "return", "new", "await", "throw", "do",
+    "sizeof", "catch",
+];
 
-    signatures
+/// The identifier at the start of `token`, after any `self.` receiver.
+fn identifier(token: &str) -> Option<String> {
+    let token = token.trim_start_matches(['*', '&']);
+    let end = token
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '$'))
+        .unwrap_or(token.len());
+    let name = token[..end].rsplit('.').next().unwrap_or("");
+    (!name.is_empty()).then(|| name.to_string())
 }
 
 fn extract_rust_signature(line: &str) -> Option<String> {
@@ -561,6 +1245,88 @@ mod tests {
         assert!(sigs.iter().any(|s| s.contains("export class Server")));
     }
 
+    #[test]
+    fn test_classify_signatures() {
+        let cases = [
+            (
+                "pub async fn parse_args<T>(x: T)",
+                SymbolKind::Function,
+                "parse_args",
+            ),
+            ("pub(crate) struct Config", SymbolKind::Struct, "Config"),
+            (
+                "impl<T> Display for Wrapper<T>",
+                SymbolKind::Impl,
+                "Wrapper",
+            ),
+            ("pub trait Tool: Send", SymbolKind::Trait, "Tool"),
+            ("class Handler(Base)", SymbolKind::Class, "Handler"),
+            ("def self.build", SymbolKind::Function, "build"),
+            (
+                "func (s *Server) Start() error",
+                SymbolKind::Function,
+                "Start",
+            ),
+            ("type Store interface", SymbolKind::Interface, "Store"),
+            (
+                "export default function render(props)",
+                SymbolKind::Function,
+                "render",
+            ),
+            (
+                "public static void main(String[] args)",
+                SymbolKind::Function,
+                "main",
+            ),
+            (
+                "const handler = async (req) => {",
+                SymbolKind::Function,
+                "handler",
+            ),
+        ];
+        for (signature, kind, name) in cases {
+            assert_eq!(
+                classify_signature(signature),
+                Some((kind, name.to_string())),
+                "{}",
+                signature
+            );
+        }
+    }
+
+    #[test]
+    fn test_language_for_path() {
+        assert_eq!(language_for_path("src/main.rs"), Some("rust"));
+        assert_eq!(language_for_path("web/App.TSX"), Some("typescript"));
+        assert_eq!(language_for_path("Makefile"), None);
+        assert_eq!(SymbolKind::parse("Method"), Some(SymbolKind::Function));
+        assert_eq!(SymbolKind::parse("widget"), None);
+    }
+
+    #[test]
+    fn test_index_workspace_records_symbols() {
+        let (_dir, path) = setup_test_workspace();
+        let search_config = SearchConfig {
+            index_path: path.join(".rustant/search_index"),
+            db_path: path.join(".rustant/vectors.db"),
+            ..Default::default()
+        };
+        let mut indexer = ProjectIndexer::new(path, search_config).unwrap();
+        indexer.index_workspace();
+
+        assert!(indexer.files().iter().any(|f| f == "src/lib.rs"));
+        let config = indexer
+            .symbols()
+            .iter()
+            .find(|s| s.name == "Config" && s.kind == SymbolKind::Struct)
+            .unwrap();
+        assert_eq!((config.path.as_str(), config.line), ("src/lib.rs", 3));
+
+        let enclosing = indexer.enclosing_symbol("src/lib.rs", 9).unwrap();
+        assert_eq!(enclosing.signature, "pub fn new() -> Self");
+        assert!(indexer.enclosing_symbol("src/lib.rs", 0).is_none());
+    }
+
     #[test]
     fn test_index_workspace() {
         let (_dir, path) = setup_test_workspace();
@@ -691,4 +1457,78 @@ mod tests {
             "Files in target/ should be ignored by .gitignore"
         );
     }
+
+    #[test]
+    fn test_rustantignore_and_config_ignores() {
+        let (_dir, path) = setup_test_workspace();
+        fs::create_dir_all(path.join("generated")).unwrap();
+        fs::write(path.join("generated/api.rs"), "pub fn generated() {}\n").unwrap();
+        fs::write(path.join(".rustantignore"), "generated/\n").unwrap();
+        fs::write(path.join("src/.rustantignore"), "fixtures.json\n").unwrap();
+        fs::write(path.join("src/fixtures.json"), "{}").unwrap();
+        fs::write(path.join("schema.sql"), "CREATE TABLE t (id INT);").unwrap();
+        let config = IndexerConfig {
+            ignore: vec!["*.sql".into()],
+            ..Default::default()
+        };
+
+        let (files, report) = survey_workspace(&path, &config);
+        assert!(files.contains(&"src/main.rs".to_string()));
+        assert!(!files.iter().any(|f| f.starts_with("generated")));
+        assert!(!files.contains(&"src/fixtures.json".to_string()));
+        assert!(!files.contains(&"schema.sql".to_string()));
+        assert_eq!(
+            report.ignore_files,
+            vec![
+                ".rustantignore".to_string(),
+                "src/.rustantignore".to_string()
+            ]
+        );
+
+        let status = explain_path(&path, &config, Path::new("generated/api.rs"));
+        assert_eq!(
+            status,
+            PathStatus::Ignored(IgnoredBy {
+                source: ".rustantignore".into(),
+                pattern: "generated/".into(),
+            })
+        );
+        let status = explain_path(&path, &config, Path::new("schema.sql"));
+        assert!(matches!(status, PathStatus::Ignored(by) if by.source == "[index] ignore"));
+        assert_eq!(
+            explain_path(&path, &config, Path::new("src/main.rs")),
+            PathStatus::Indexed
+        );
+        assert_eq!(
+            explain_path(&path, &config, Path::new("src/missing.rs")),
+            PathStatus::Absent
+        );
+    }
+
+    #[test]
+    fn test_directory_budget_truncates() {
+        let (_dir, path) = setup_test_workspace();
+        fs::create_dir_all(path.join("fixtures")).unwrap();
+        for name in ["a", "b", "c", "d", "e"] {
+            fs::write(path.join(format!("fixtures/{}.json", name)), "{}").unwrap();
+        }
+        let config = IndexerConfig {
+            max_files_per_dir: 2,
+            ..Default::default()
+        };
+
+        let (files, report) = survey_workspace(&path, &config);
+        assert!(files.contains(&"fixtures/a.json".to_string()));
+        assert!(!files.contains(&"fixtures/e.json".to_string()));
+        let truncation = report
+            .truncated
+            .iter()
+            .find(|t| t.dir == "fixtures")
+            .unwrap();
+        assert_eq!((truncation.indexed, truncation.skipped), (2, 3));
+        assert!(matches!(
+            explain_path(&path, &config, Path::new("fixtures/e.json")),
+            PathStatus::Truncated(t) if t.dir == "fixtures"
+        ));
+    }
 }
@@ -5,18 +5,34 @@
 //! safety guardian, configuration, and fundamental types.
 
 pub mod agent;
+pub mod approval_timeout;
+pub mod artifacts;
+pub mod attachments;
 pub mod audit;
 pub mod brain;
+pub mod briefing;
 pub mod browser;
+pub mod cache;
 pub mod canvas;
+pub mod capabilities;
 pub mod channels;
+pub mod clipboard;
 pub mod config;
+pub mod contracts;
+pub mod cost_preflight;
 pub mod council;
 pub mod credentials;
+pub mod custom_commands;
+pub mod distillation;
 pub mod encryption;
 pub mod error;
+pub mod evaluation;
+pub mod event_store;
 pub mod explanation;
+pub mod fast_path;
+pub mod focus;
 pub mod gateway;
+pub mod incidents;
 pub mod indexer;
 pub mod injection;
 pub mod memory;
@@ -26,10 +42,14 @@ pub mod multi;
 pub mod nodes;
 pub mod oauth;
 pub mod pairing;
+pub mod people;
+pub mod personas;
+pub mod pins;
 pub mod plan;
 pub mod project_detect;
 pub mod providers;
 pub mod replay;
+pub mod response_validation;
 pub mod safety;
 pub mod sandbox;
 pub mod sanitize;
@@ -37,35 +57,52 @@ pub mod scheduler;
 pub mod search;
 pub mod secret_ref;
 pub mod session_manager;
+pub mod similarity;
 pub mod skills;
+pub mod subtasks;
 pub mod summarizer;
+pub mod task_checkpoint;
+pub mod telemetry;
+pub mod tool_aliases;
+pub mod tool_call;
+pub mod tool_validation;
+pub mod trust;
 pub mod types;
 pub mod updater;
 pub mod voice;
 pub mod workflow;
+pub mod workspaces;
 
 // Re-export commonly used types at the crate root.
 pub use agent::{
     Agent, AgentCallback, AgentMessage, BudgetSeverity, ContextHealthEvent, NoOpCallback,
     RegisteredTool, TaskResult,
 };
-pub use brain::{Brain, LlmProvider, MockLlmProvider, TokenCounter};
+pub use artifacts::{ArtifactKind, ArtifactRecord, summarize_artifacts};
+pub use attachments::{AttachmentError, AttachmentStore};
+pub use brain::{Brain, ImagePricing, LlmProvider, MockLlmProvider, TokenCounter};
 #[cfg(feature = "browser")]
 pub use browser::ChromiumCdpClient;
 pub use browser::{
     BrowserSecurityGuard, BrowserSession, CdpClient, MockCdpClient, PageSnapshot, SnapshotMode,
 };
-pub use channels::cdc::{CdcAction, CdcConfig, CdcProcessor, CdcState};
+pub use channels::cdc::{
+    CdcAction, CdcConfig, CdcFacts, CdcProcessor, CdcSourceConfig, CdcState, SourceSyncState,
+};
 pub use channels::style_tracker::{CommunicationStyleTracker, SenderStyleProfile};
 pub use channels::{
     AutoReplyEngine, Channel, ChannelAgentBridge, ChannelCapabilities, ChannelDigest,
     ChannelManager, ChannelMessage, ChannelStatus, ChannelType, ChannelUser, ClassificationCache,
-    ClassifiedMessage, DigestActionItem, DigestCollector, DigestHighlight, EmailCategory,
-    EmailClassification, EmailIntelligence, FollowUpReminder, IMessageChannel, IMessageConfig,
-    IntelligenceResult, IrcChannel, IrcConfig, LlmClassificationResponse, MessageClassifier,
-    MessageContent, MessageId, MessageType, PendingReply, ReminderStatus, ReplyStatus,
-    ResolvedContact, SchedulerBridge, SenderProfile, SmsChannel, SmsConfig, StreamingMode,
-    SuggestedAction, TeamsChannel, TeamsConfig, ThreadId, WebhookChannel, WebhookConfig,
+    ClassifiedMessage, DigestActionItem, DigestCollector, DigestDelivery, DigestHighlight,
+    DigestRunner, DigestScheduler, EmailCategory, EmailClassification, EmailIntelligence,
+    FollowUpReminder, IMessageChannel, IMessageConfig, IntelligenceResult, IrcChannel, IrcConfig,
+    LlmClassificationResponse, MessageClassifier, MessageContent, MessageId, MessageType,
+    PendingReply, ReminderStatus, ReplyStatus, ResolvedContact, SchedulerBridge, SenderProfile,
+    SmsChannel, SmsConfig, StreamingMode, SuggestedAction, TeamsChannel, TeamsConfig,
+    ThreadContext, ThreadContextConfig, ThreadId, WebhookChannel, WebhookConfig,
+};
+pub use clipboard::{
+    ClipEntry, ClipKind, ClipboardConfig, ClipboardError, ClipboardHistory, SensitivePolicy,
 };
 pub use config::MultiAgentConfig;
 pub use config::{
@@ -73,20 +110,42 @@ pub use config::{
     KnowledgeConfig, VotingStrategy, config_exists,
 };
 pub use config::{
-    AutoReplyMode, ChannelIntelligenceConfig, DigestFrequency, IntelligenceConfig,
-    MessagePriority as ChannelMessagePriority,
+    AutoReplyMode, ChannelIntelligenceConfig, DigestDeliveryConfig, DigestFrequency, DigestSection,
+    DigestTemplate, IntelligenceConfig, MessagePriority as ChannelMessagePriority,
 };
 pub use council::{
     CouncilMemberResponse, CouncilResult, DetectedProvider, PeerReview, PlanningCouncil,
     detect_available_providers, should_use_council,
 };
+pub use fast_path::{FastPathConfig, FastRoute};
+pub use people::{
+    PeopleError, Person, PersonGraph, PersonHandle, PersonObservation, RelationshipSuggestion,
+    SuggestionConfig,
+};
+pub use personas::{PersonaConfig, PersonaError, PersonaRegistry};
 pub use plan::{
     ExecutionPlan, PlanAlternative, PlanConfig, PlanDecision, PlanStatus, PlanStep, StepStatus,
 };
 pub use tokio_util::sync::CancellationToken;
 
+pub use briefing::{
+    Briefing, BriefingConfig, BriefingContext, BriefingDelivery, BriefingEngine, BriefingError,
+    BriefingPeriod, BriefingSection, BriefingSectionConfig,
+};
+pub use capabilities::{
+    CapabilityCheck, CapabilityDenial, CapabilityGrant, CapabilityPolicy, ParanoidConfig,
+    summarize_grants,
+};
+pub use contracts::{
+    ContractError, ContractPhase, ContractSet, ContractViolation, ToolContract, ToolContractConfig,
+};
 pub use credentials::{
-    CredentialError, CredentialStore, InMemoryCredentialStore, KeyringCredentialStore,
+    CredentialError, CredentialFileConfig, CredentialStore, CredentialStoreKind,
+    EnvCredentialStore, FileCredentialStore, InMemoryCredentialStore, KeyringCredentialStore,
+    configured_store,
+};
+pub use custom_commands::{
+    CustomCommand, CustomCommandConfig, CustomCommandError, CustomCommandSet,
 };
 pub use encryption::{EncryptionError, SessionEncryptor};
 pub use error::BrowserError;
@@ -98,7 +157,14 @@ pub use explanation::{DecisionExplanation, DecisionType, ExplanationBuilder, Fac
 pub use gateway::{
     ChannelBridge, ClientMessage, GatewayConfig, GatewayEvent, NodeBridge, ServerMessage,
 };
-pub use indexer::{IndexStats, IndexerConfig, ProjectIndexer};
+pub use incidents::{
+    Executor, IncidentError, IncidentRecord, IncidentSeverity, IncidentStore, IncidentsConfig,
+    Runbook,
+};
+pub use indexer::{
+    IndexConfig, IndexStats, IndexerConfig, PathStatus, ProjectIndexer, SkipReport, Symbol,
+    SymbolKind, WorkspaceIgnore,
+};
 pub use injection::{
     InjectionDetector, InjectionScanResult, InjectionType, Severity as InjectionSeverity,
 };
@@ -110,7 +176,8 @@ pub use merkle::{AuditNode, MerkleChain, VerificationResult};
 pub use multi::AgentStatus as MultiAgentStatus;
 pub use multi::{
     AgentContext, AgentEnvelope, AgentOrchestrator, AgentPayload, AgentRoute, AgentRouter,
-    AgentSpawner, MessageBus, MessagePriority, ResourceLimits, TaskHandler,
+    AgentSpawner, MemoryMerge, MessageBus, MessagePriority, ResourceLimits, ScopedTools,
+    SpawnRequest, TaskHandler, TaskScope,
 };
 pub use nodes::{
     Capability, ConsentEntry, ConsentStore, DiscoveredNode, Node, NodeCapability, NodeDiscovery,
@@ -118,12 +185,14 @@ pub use nodes::{
 };
 pub use oauth::AuthMethod;
 pub use pairing::{DeviceIdentity, PairingChallenge, PairingManager, PairingResult};
+pub use pins::{ContextPin, PinSet, PinStatus, PinTarget, PinnedContext, RenderedPin};
 pub use project_detect::{
-    ProjectInfo, ProjectType, detect_project, example_tasks, recommended_allowed_commands,
+    ProjectInfo, ProjectType, Subproject, detect_project, detect_subprojects, example_tasks,
+    recommended_allowed_commands,
 };
 pub use providers::{
     CircuitBreaker, CircuitState, FailoverProvider, GeminiProvider, ModelInfo,
-    create_council_members, create_provider, create_provider_with_auth,
+    create_council_members, create_provider, create_provider_with_auth, create_vision_provider,
 };
 pub use safety::{
     AdaptiveTrust, ApprovalContext, ApprovalDecision, BehavioralFingerprint, ContractEnforcer,
@@ -133,28 +202,39 @@ pub use safety::{
 pub use sandbox::SandboxedFs;
 pub use scheduler::{
     BackgroundJob, CronJob, CronJobConfig, CronScheduler, HeartbeatConfig, HeartbeatManager,
-    JobManager, JobStatus, WebhookEndpoint, WebhookHandler,
+    JobHistory, JobManager, JobOrigin, JobRun, JobStatus, KillSwitch, OverlapPolicy, RunOutcome,
+    WebhookEndpoint, WebhookHandler,
 };
 pub use search::{HybridSearchEngine, SearchConfig, SearchResult};
 pub use secret_ref::{MigrationResult, SecretRef, SecretResolveError, SecretResolver};
-pub use session_manager::{SessionEntry, SessionIndex, SessionManager};
+pub use session_manager::{
+    BranchChange, BranchComparison, BranchDiff, BranchPoint, SessionEntry, SessionIndex,
+    SessionManager, SessionsConfig,
+};
+pub use similarity::{
+    Signature, SimilarityConfig, SimilarityScore, SimilarityService, SimilarityStrategy,
+};
 pub use skills::{
     ParseError as SkillParseError, SkillConfig, SkillDefinition, SkillLoader, SkillRegistry,
     SkillRequirement, SkillRiskLevel, SkillToolDef, ValidationError, ValidationResult,
     parse_skill_md, validate_skill,
 };
 pub use summarizer::{ContextSummarizer, ContextSummary, TokenAlert, TokenCostDisplay};
+pub use tool_aliases::{AliasStage, AliasTable, ToolAlias};
+pub use tool_call::{ArgSummarizer, SlowCallCanceller};
 pub use types::{
-    AgentState, AgentStatus, Artifact, CompletionRequest, CompletionResponse, Content,
-    CostEstimate, Message, ProgressUpdate, RiskLevel, Role, StreamEvent, TaskClassification,
-    TokenUsage, ToolDefinition, ToolOutput,
+    AgentState, AgentStatus, Artifact, Attachment, AttachmentHandle, CompletionRequest,
+    CompletionResponse, Content, CostEstimate, ImageSource, Message, ProgressUpdate, RiskLevel,
+    Role, StreamEvent, TaskClassification, TokenUsage, ToolDefinition, ToolErrorKind, ToolFailure,
+    ToolOutput,
 };
 pub use voice::{
-    AudioChunk, AudioFormat, MeetingRecordingSession, MeetingResult, MeetingStatus,
+    AudioChunk, AudioFormat, LiveTranscript, MeetingRecordingSession, MeetingResult, MeetingStatus,
     MockSttProvider, MockTtsProvider, MockWakeDetector, OpenAiSttProvider, OpenAiTtsProvider,
-    SttProvider, SttWakeDetector, SynthesisRequest, SynthesisResult, ToggleState,
-    TranscriptionResult, TranscriptionSegment, TtsProvider, VadEvent, VoiceActivityDetector,
-    VoiceCommandSession, WakeWordDetector, audio_convert,
+    SpeakerDiarizer, StreamingTranscriber, SttProvider, SttWakeDetector, SynthesisRequest,
+    SynthesisResult, ToggleState, TranscriptionMode, TranscriptionResult, TranscriptionSegment,
+    TtsProvider, VadEvent, VoiceActivityDetector, VoiceCommandSession, VoiceConfirmation,
+    VoiceDenial, VoiceTurn, WakeWordDetector, WhisperConfig, audio_convert,
 };
 #[cfg(feature = "voice")]
 pub use voice::{
@@ -165,6 +245,7 @@ pub use workflow::{
     WorkflowDefinition, WorkflowExecutor, WorkflowState, WorkflowStatus, get_builtin,
     list_builtin_names, parse_workflow, validate_workflow,
 };
+pub use workspaces::{NamedWorkspace, WorkspaceError, WorkspaceRegistry};
 
 #[cfg(test)]
 mod reexport_tests {
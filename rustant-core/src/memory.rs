@@ -5,12 +5,15 @@
 //! - **Long-Term Memory**: Persistent facts and preferences across sessions.
 
 use crate::error::MemoryError;
-use crate::search::{HybridSearchEngine, SearchConfig};
+use crate::pins::PinSet;
+use crate::search::{HybridSearchEngine, SearchConfig, SimpleEmbedder};
+use crate::similarity::{Signature, SimilarityConfig, SimilarityScore, SimilarityService};
 use crate::types::{Content, Message, Role};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::Path;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Working memory for the currently executing task.
@@ -522,6 +525,12 @@ pub struct MemorySystem {
     search_engine: Option<HybridSearchEngine>,
     /// Optional automatic flusher for periodic persistence.
     flusher: Option<MemoryFlusher>,
+    /// Files and facts pinned into every prompt.
+    pub pins: PinSet,
+    /// Duplicate detection for incoming facts.
+    similarity: SimilarityService,
+    /// Signatures of known facts, computed on first comparison.
+    fact_signatures: HashMap<Uuid, Signature>,
 }
 
 impl MemorySystem {
@@ -532,6 +541,9 @@ impl MemorySystem {
             long_term: LongTermMemory::new(),
             search_engine: None,
             flusher: None,
+            pins: PinSet::default(),
+            similarity: SimilarityService::new(SimilarityConfig::facts()),
+            fact_signatures: HashMap::new(),
         }
     }
 
@@ -540,6 +552,7 @@ impl MemorySystem {
         window_size: usize,
         search_config: SearchConfig,
     ) -> Result<Self, crate::search::SearchError> {
+        let embedder = SimpleEmbedder::new(search_config.vector_dimensions);
         let engine = HybridSearchEngine::open(search_config)?;
         Ok(Self {
             working: WorkingMemory::new(),
@@ -547,6 +560,10 @@ impl MemorySystem {
             long_term: LongTermMemory::new(),
             search_engine: Some(engine),
             flusher: None,
+            pins: PinSet::default(),
+            similarity: SimilarityService::new(SimilarityConfig::facts())
+                .with_embedder(Arc::new(embedder)),
+            fact_signatures: HashMap::new(),
         })
     }
 
@@ -571,11 +588,41 @@ impl MemorySystem {
     }
 
     /// Add a fact to long-term memory, also indexing it in the search engine.
-    pub fn add_fact(&mut self, fact: Fact) {
+    ///
+    /// A fact that duplicates a known one is not added; the known fact's ID
+    /// and why it matched are returned instead. With search enabled, facts
+    /// are also compared by embedding.
+    pub fn add_fact(&mut self, fact: Fact) -> Option<(Uuid, SimilarityScore)> {
+        for known in &self.long_term.facts {
+            self.fact_signatures
+                .entry(known.id)
+                .or_insert_with(|| Signature::of(&known.content));
+        }
+        let signature = Signature::of(&fact.content);
+        let signatures = &self.fact_signatures;
+        let duplicate = self.similarity.best_match(
+            &fact.content,
+            &signature,
+            self.long_term
+                .facts
+                .iter()
+                .filter_map(|f| signatures.get(&f.id).map(|s| (f.id, f.content.as_str(), s))),
+        );
+        if let Some((existing, score)) = duplicate {
+            tracing::debug!(%existing, reason = %score, "Skipping duplicate fact");
+            return Some((existing, score));
+        }
+
+        if self.fact_signatures.len() > self.long_term.facts.len() * 2 {
+            let known: HashSet<Uuid> = self.long_term.facts.iter().map(|f| f.id).collect();
+            self.fact_signatures.retain(|id, _| known.contains(id));
+        }
+        self.fact_signatures.insert(fact.id, signature);
         if let Some(ref mut engine) = self.search_engine {
             let _ = engine.index_fact(&fact.id.to_string(), &fact.content);
         }
         self.long_term.add_fact(fact);
+        None
     }
 
     /// Search facts using the hybrid engine (falls back to keyword search).
@@ -638,7 +685,7 @@ impl MemorySystem {
         self.working.set_goal(goal);
     }
 
-    /// Clear everything except long-term memory.
+    /// Clear everything except long-term memory and pins.
     pub fn clear_session(&mut self) {
         self.working.clear();
         self.short_term.clear();
@@ -653,12 +700,39 @@ impl MemorySystem {
             .iter()
             .map(|m| m.content_length())
             .sum();
-        let total_chars = summary_chars + message_chars;
 
         // Rough token estimate: ~4 chars per token
-        let summary_tokens = summary_chars / 4;
-        let message_tokens = message_chars / 4;
-        let total_tokens = total_chars / 4;
+        self.breakdown_from_tokens(context_window, summary_chars / 4, message_chars / 4)
+    }
+
+    /// Like [`context_breakdown`](Self::context_breakdown), but counted with
+    /// the model's tokenizer instead of the ~4 chars per token estimate.
+    pub fn context_breakdown_with(
+        &self,
+        context_window: usize,
+        counter: &crate::brain::TokenCounter,
+    ) -> ContextBreakdown {
+        let summary_tokens = self
+            .short_term
+            .summary()
+            .map(|s| counter.count(s))
+            .unwrap_or(0);
+        let message_tokens = self
+            .short_term
+            .messages()
+            .iter()
+            .map(|m| counter.count_message(m))
+            .sum();
+        self.breakdown_from_tokens(context_window, summary_tokens, message_tokens)
+    }
+
+    fn breakdown_from_tokens(
+        &self,
+        context_window: usize,
+        summary_tokens: usize,
+        message_tokens: usize,
+    ) -> ContextBreakdown {
+        let total_tokens = summary_tokens + message_tokens;
         let remaining_tokens = context_window.saturating_sub(total_tokens);
 
         ContextBreakdown {
@@ -670,6 +744,7 @@ impl MemorySystem {
             message_count: self.short_term.len(),
             total_messages_seen: self.short_term.total_messages_seen(),
             pinned_count: self.short_term.pinned_count(),
+            pinned_tokens: 0,
             has_summary: self.short_term.summary().is_some(),
             facts_count: self.long_term.facts.len(),
             rules_count: 0, // Populated separately if knowledge distiller is available
@@ -706,6 +781,8 @@ pub struct ContextBreakdown {
     pub total_messages_seen: usize,
     /// Number of pinned messages.
     pub pinned_count: usize,
+    /// Estimated tokens used by pinned files and facts.
+    pub pinned_tokens: usize,
     /// Whether a summary prefix exists.
     pub has_summary: bool,
     /// Number of facts in long-term memory.
@@ -728,6 +805,13 @@ impl ContextBreakdown {
     pub fn is_warning(&self) -> bool {
         self.usage_ratio() >= 0.7
     }
+
+    /// Count pinned context, which is sent with every request, as in use.
+    pub fn add_pinned(&mut self, tokens: usize) {
+        self.pinned_tokens += tokens;
+        self.total_tokens += tokens;
+        self.remaining_tokens = self.context_window.saturating_sub(self.total_tokens);
+    }
 }
 
 /// Metadata about a saved session.
@@ -737,6 +821,9 @@ pub struct SessionMetadata {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub task_summary: Option<String>,
+    /// Context pins, restored on resume.
+    #[serde(default)]
+    pub pins: PinSet,
 }
 
 impl SessionMetadata {
@@ -747,6 +834,7 @@ impl SessionMetadata {
             created_at: now,
             updated_at: now,
             task_summary: None,
+            pins: PinSet::default(),
         }
     }
 }
@@ -768,14 +856,15 @@ pub struct Session {
 }
 
 impl MemorySystem {
-    /// Save the current memory state to a JSON file.
-    pub fn save_session(&self, path: &Path) -> Result<(), MemoryError> {
+    /// Serialize the current memory state as session JSON.
+    pub fn to_session_json(&self) -> Result<String, MemoryError> {
         let session = Session {
             metadata: SessionMetadata {
                 id: Uuid::new_v4(),
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
                 task_summary: self.working.current_goal.clone(),
+                pins: self.pins.clone(),
             },
             working: self.working.clone(),
             long_term: self.long_term.clone(),
@@ -783,11 +872,33 @@ impl MemorySystem {
             window_size: self.short_term.window_size(),
         };
 
-        let json =
-            serde_json::to_string_pretty(&session).map_err(|e| MemoryError::PersistenceError {
-                message: format!("Failed to serialize session: {}", e),
+        serde_json::to_string_pretty(&session).map_err(|e| MemoryError::PersistenceError {
+            message: format!("Failed to serialize session: {}", e),
+        })
+    }
+
+    /// Rebuild a memory system from session JSON.
+    pub fn from_session_json(json: &str) -> Result<Self, MemoryError> {
+        let session: Session =
+            serde_json::from_str(json).map_err(|e| MemoryError::SessionLoadFailed {
+                message: format!("Failed to deserialize session: {}", e),
             })?;
 
+        let mut memory = MemorySystem::new(session.window_size);
+        memory.working = session.working;
+        memory.long_term = session.long_term;
+        memory.pins = session.metadata.pins;
+        for msg in session.messages {
+            memory.short_term.add(msg);
+        }
+
+        Ok(memory)
+    }
+
+    /// Save the current memory state to a JSON file.
+    pub fn save_session(&self, path: &Path) -> Result<(), MemoryError> {
+        let json = self.to_session_json()?;
+
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent).map_err(|e| MemoryError::PersistenceError {
                 message: format!("Failed to create directory: {}", e),
@@ -806,20 +917,7 @@ impl MemorySystem {
         let json = std::fs::read_to_string(path).map_err(|e| MemoryError::SessionLoadFailed {
             message: format!("Failed to read session file: {}", e),
         })?;
-
-        let session: Session =
-            serde_json::from_str(&json).map_err(|e| MemoryError::SessionLoadFailed {
-                message: format!("Failed to deserialize session: {}", e),
-            })?;
-
-        let mut memory = MemorySystem::new(session.window_size);
-        memory.working = session.working;
-        memory.long_term = session.long_term;
-        for msg in session.messages {
-            memory.short_term.add(msg);
-        }
-
-        Ok(memory)
+        Self::from_session_json(&json)
     }
 }
 
@@ -1171,7 +1269,7 @@ impl KnowledgeDistiller {
         if self.store.rules.len() > self.max_rules {
             self.store
                 .rules
-                .sort_by(|a, b| b.support_count.cmp(&a.support_count));
+                .sort_by_key(|r| std::cmp::Reverse(r.support_count));
             self.store.rules.truncate(self.max_rules);
         }
 
@@ -1212,6 +1310,7 @@ impl KnowledgeDistiller {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::similarity::SimilarityStrategy;
     use crate::types::{Content, Role};
 
     #[test]
@@ -1459,6 +1558,23 @@ mod tests {
         assert_eq!(messages[1].content.as_text(), Some("Looking into it."));
     }
 
+    #[test]
+    fn test_session_restores_context_pins() {
+        let mut mem = MemorySystem::new(10);
+        mem.pins.pin(
+            crate::pins::PinTarget::Fact {
+                text: "The API contract is frozen".into(),
+            },
+            false,
+        );
+        mem.clear_session();
+
+        let json = mem.to_session_json().unwrap();
+        let loaded = MemorySystem::from_session_json(&json).unwrap();
+        let labels: Vec<String> = loaded.pins.iter().map(|p| p.label()).collect();
+        assert_eq!(labels, vec!["The API contract is frozen"]);
+    }
+
     #[test]
     fn test_session_load_missing_file() {
         let result = MemorySystem::load_session(Path::new("/nonexistent/session.json"));
@@ -1791,6 +1907,7 @@ mod tests {
             full_text_weight: 0.5,
             vector_weight: 0.5,
             max_results: 10,
+            ..Default::default()
         };
         let mut mem = MemorySystem::with_search(10, config).unwrap();
 
@@ -1831,6 +1948,7 @@ mod tests {
             full_text_weight: 0.5,
             vector_weight: 0.5,
             max_results: 10,
+            ..Default::default()
         };
         let mut mem = MemorySystem::with_search(10, config).unwrap();
 
@@ -1843,6 +1961,25 @@ mod tests {
         assert_eq!(mem.long_term.facts.len(), 5);
     }
 
+    #[test]
+    fn test_add_fact_skips_duplicates() {
+        let mut mem = MemorySystem::new(10);
+        let original = Fact::new("Tests run with cargo nextest", "session");
+        let original_id = original.id;
+        assert!(mem.add_fact(original).is_none());
+
+        let (existing, score) = mem
+            .add_fact(Fact::new("run tests with Cargo nextest.", "distilled"))
+            .unwrap();
+        assert_eq!(existing, original_id);
+        assert_eq!(score.strategy, SimilarityStrategy::TokenSet);
+        assert!(
+            mem.add_fact(Fact::new("Tests run with cargo test", "session"))
+                .is_none()
+        );
+        assert_eq!(mem.long_term.facts.len(), 2);
+    }
+
     // --- A5: MemoryFlusher → MemorySystem integration tests ---
 
     #[test]
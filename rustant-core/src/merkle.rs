@@ -219,7 +219,7 @@ impl MerkleChain {
 // ---------------------------------------------------------------------------
 
 /// Compute SHA-256 of arbitrary bytes and return hex string.
-fn hex_sha256(data: &[u8]) -> String {
+pub(crate) fn hex_sha256(data: &[u8]) -> String {
     let mut hasher = Sha256::new();
     hasher.update(data);
     format!("{:x}", hasher.finalize())
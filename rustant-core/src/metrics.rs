@@ -1,10 +1,299 @@
 //! Agent metrics — counters and histograms for observability.
 //!
-//! When the `metrics` feature is enabled, this module provides OpenTelemetry
-//! integration.  When disabled (the default), all operations are no-ops so
-//! there is zero runtime overhead.
+//! [`AgentMetrics`] keeps local counters. The `record_*` functions feed the
+//! OpenTelemetry instruments installed by [`crate::telemetry::Telemetry`]
+//! when the `otel` feature is enabled and `[telemetry]` is configured.
+//! Otherwise they are no-ops.
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "otel")]
+mod instruments {
+    use opentelemetry::KeyValue;
+    use opentelemetry::metrics::{Counter, Histogram, Meter};
+    use std::sync::{Arc, RwLock};
+
+    pub(super) struct Instruments {
+        pub tool_calls: Counter<u64>,
+        pub tool_duration: Histogram<f64>,
+        pub llm_requests: Counter<u64>,
+        pub llm_duration: Histogram<f64>,
+        pub tokens: Counter<u64>,
+        pub cost: Counter<f64>,
+        pub failovers: Counter<u64>,
+        pub cache_lookups: Counter<u64>,
+        pub tool_validation_failures: Counter<u64>,
+        pub tool_alias_calls: Counter<u64>,
+        pub unknown_tool_calls: Counter<u64>,
+        pub tool_failures: Counter<u64>,
+        pub task_duration: Histogram<f64>,
+        pub gateway_disconnects: Counter<u64>,
+    }
+
+    /// Replaced on each install so a re-initialized exporter takes over.
+    static INSTRUMENTS: RwLock<Option<Arc<Instruments>>> = RwLock::new(None);
+
+    pub(super) fn install(meter: &Meter) {
+        let instruments = Instruments {
+            tool_calls: meter
+                .u64_counter("rustant.tool.calls")
+                .with_description("Tool invocations")
+                .build(),
+            tool_duration: meter
+                .f64_histogram("rustant.tool.duration")
+                .with_description("Tool execution time")
+                .with_unit("s")
+                .build(),
+            llm_requests: meter
+                .u64_counter("rustant.llm.requests")
+                .with_description("LLM completion requests")
+                .build(),
+            llm_duration: meter
+                .f64_histogram("rustant.llm.duration")
+                .with_description("LLM request latency")
+                .with_unit("s")
+                .build(),
+            tokens: meter
+                .u64_counter("rustant.llm.tokens")
+                .with_description("Tokens consumed")
+                .build(),
+            cost: meter
+                .f64_counter("rustant.llm.cost")
+                .with_description("Estimated LLM cost")
+                .with_unit("USD")
+                .build(),
+            failovers: meter
+                .u64_counter("rustant.llm.failovers")
+                .with_description("Provider failures that triggered failover")
+                .build(),
+            cache_lookups: meter
+                .u64_counter("rustant.llm.cache")
+                .with_description("LLM response cache lookups by outcome")
+                .build(),
+            tool_validation_failures: meter
+                .u64_counter("rustant.tool.validation_failures")
+                .with_description("Tool calls rejected by the tool's argument schema")
+                .build(),
+            tool_alias_calls: meter
+                .u64_counter("rustant.tool.alias_calls")
+                .with_description("Tool calls made through a deprecated alias")
+                .build(),
+            unknown_tool_calls: meter
+                .u64_counter("rustant.llm.unknown_tool_calls")
+                .with_description("Calls to tools that do not exist, by model and outcome")
+                .build(),
+            tool_failures: meter
+                .u64_counter("rustant.tool.failures")
+                .with_description(
+                    "Failed tool executions by error kind, including retried attempts",
+                )
+                .build(),
+            task_duration: meter
+                .f64_histogram("rustant.task.duration")
+                .with_description("Task wall-clock time by route (fast_path, fallback, full)")
+                .with_unit("s")
+                .build(),
+            gateway_disconnects: meter
+                .u64_counter("rustant.gateway.disconnects")
+                .with_description("Closed gateway connections by reason (closed, stale, kicked)")
+                .build(),
+        };
+        *INSTRUMENTS.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(instruments));
+    }
+
+    pub(super) fn get() -> Option<Arc<Instruments>> {
+        INSTRUMENTS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub(super) fn outcome(success: bool) -> KeyValue {
+        KeyValue::new("outcome", if success { "success" } else { "error" })
+    }
+}
+
+/// Install the OpenTelemetry instruments used by the `record_*` functions.
+#[cfg(feature = "otel")]
+pub(crate) fn install_instruments(meter: &opentelemetry::metrics::Meter) {
+    instruments::install(meter);
+}
+
+/// Record a finished tool execution.
+pub fn record_tool_call(tool: &str, success: bool, duration: Duration) {
+    #[cfg(feature = "otel")]
+    if let Some(i) = instruments::get() {
+        use opentelemetry::KeyValue;
+        let tool = KeyValue::new("tool_name", tool.to_string());
+        i.tool_calls
+            .add(1, &[tool.clone(), instruments::outcome(success)]);
+        i.tool_duration.record(duration.as_secs_f64(), &[tool]);
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = (tool, success, duration);
+}
+
+/// Record a finished LLM completion request.
+pub fn record_llm_request(model: &str, success: bool, duration: Duration) {
+    #[cfg(feature = "otel")]
+    if let Some(i) = instruments::get() {
+        use opentelemetry::KeyValue;
+        let model = KeyValue::new("model", model.to_string());
+        i.llm_requests
+            .add(1, &[model.clone(), instruments::outcome(success)]);
+        i.llm_duration.record(duration.as_secs_f64(), &[model]);
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = (model, success, duration);
+}
+
+/// Record token usage and its estimated cost in USD.
+pub fn record_token_usage(model: &str, input: u64, output: u64, cost_usd: f64) {
+    #[cfg(feature = "otel")]
+    if let Some(i) = instruments::get() {
+        use opentelemetry::KeyValue;
+        let model = KeyValue::new("model", model.to_string());
+        i.tokens
+            .add(input, &[model.clone(), KeyValue::new("direction", "input")]);
+        i.tokens.add(
+            output,
+            &[model.clone(), KeyValue::new("direction", "output")],
+        );
+        i.cost.add(cost_usd, &[model]);
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = (model, input, output, cost_usd);
+}
+
+/// Record a provider failure that made the failover chain try the next provider.
+pub fn record_failover(model: &str) {
+    #[cfg(feature = "otel")]
+    if let Some(i) = instruments::get() {
+        i.failovers.add(
+            1,
+            &[opentelemetry::KeyValue::new("model", model.to_string())],
+        );
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = model;
+}
+
+/// Record an LLM response cache lookup (`exact`, `semantic`, or `miss`).
+pub fn record_cache_lookup(model: &str, hit: Option<crate::cache::CacheHit>) {
+    #[cfg(feature = "otel")]
+    if let Some(i) = instruments::get() {
+        use opentelemetry::KeyValue;
+        let outcome = match hit {
+            Some(crate::cache::CacheHit::Exact) => "exact",
+            Some(crate::cache::CacheHit::Semantic { .. }) => "semantic",
+            None => "miss",
+        };
+        i.cache_lookups.add(
+            1,
+            &[
+                KeyValue::new("model", model.to_string()),
+                KeyValue::new("outcome", outcome),
+            ],
+        );
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = (model, hit);
+}
+
+/// Record a tool call whose arguments failed schema validation.
+pub fn record_tool_validation_failure(tool: &str) {
+    #[cfg(feature = "otel")]
+    if let Some(i) = instruments::get() {
+        i.tool_validation_failures.add(
+            1,
+            &[opentelemetry::KeyValue::new("tool_name", tool.to_string())],
+        );
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = tool;
+}
+
+/// Record a call to a tool that does not exist, and whether it was
+/// corrected to a registered tool or sent back to the model.
+pub fn record_unknown_tool_call(model: &str, corrected: bool) {
+    #[cfg(feature = "otel")]
+    if let Some(i) = instruments::get() {
+        use opentelemetry::KeyValue;
+        let outcome = if corrected { "corrected" } else { "rejected" };
+        i.unknown_tool_calls.add(
+            1,
+            &[
+                KeyValue::new("model", model.to_string()),
+                KeyValue::new("outcome", outcome),
+            ],
+        );
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = (model, corrected);
+}
+
+/// Record a tool call made through a deprecated alias.
+pub fn record_tool_alias_call(alias: &str, tool: &str) {
+    #[cfg(feature = "otel")]
+    if let Some(i) = instruments::get() {
+        use opentelemetry::KeyValue;
+        i.tool_alias_calls.add(
+            1,
+            &[
+                KeyValue::new("alias", alias.to_string()),
+                KeyValue::new("tool_name", tool.to_string()),
+            ],
+        );
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = (alias, tool);
+}
+
+/// Record a failed tool execution attempt by error kind.
+pub fn record_tool_failure(tool: &str, kind: crate::types::ToolErrorKind) {
+    #[cfg(feature = "otel")]
+    if let Some(i) = instruments::get() {
+        use opentelemetry::KeyValue;
+        i.tool_failures.add(
+            1,
+            &[
+                KeyValue::new("tool_name", tool.to_string()),
+                KeyValue::new("kind", kind.as_str()),
+            ],
+        );
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = (tool, kind);
+}
+
+/// Record a finished task and the route it took.
+pub fn record_task(route: &str, success: bool, duration: Duration) {
+    #[cfg(feature = "otel")]
+    if let Some(i) = instruments::get() {
+        use opentelemetry::KeyValue;
+        i.task_duration.record(
+            duration.as_secs_f64(),
+            &[
+                KeyValue::new("route", route.to_string()),
+                instruments::outcome(success),
+            ],
+        );
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = (route, success, duration);
+}
+
+/// Record a closed gateway connection and why it closed.
+pub fn record_gateway_disconnect(reason: &str) {
+    #[cfg(feature = "otel")]
+    if let Some(i) = instruments::get() {
+        use opentelemetry::KeyValue;
+        i.gateway_disconnects
+            .add(1, &[KeyValue::new("reason", reason.to_string())]);
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = reason;
+}
 
 /// Agent-level metrics for task execution, tool calls, and token usage.
 #[derive(Debug, Default)]
@@ -145,6 +434,16 @@ mod tests {
         assert!(m.uptime_secs() < 5);
     }
 
+    #[test]
+    fn test_record_functions_are_noops_without_telemetry() {
+        record_tool_call("echo", true, Duration::from_millis(5));
+        record_llm_request("mock-model", false, Duration::from_millis(5));
+        record_token_usage("mock-model", 10, 5, 0.001);
+        record_failover("mock-model");
+        record_tool_failure("web_fetch", crate::types::ToolErrorKind::TransientNetwork);
+        record_task("fast_path", true, Duration::from_millis(5));
+    }
+
     #[test]
     fn test_noop_when_default() {
         let m = AgentMetrics::default();
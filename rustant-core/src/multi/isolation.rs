@@ -1,19 +1,31 @@
-//! Agent isolation — each agent gets its own memory and safety context.
+//! Agent isolation — each agent gets its own memory, safety context and tools.
 //!
 //! `AgentContext` bundles a unique ID, name, memory system, safety guardian,
 //! and optional parent reference, ensuring agents cannot interfere with
-//! each other's state.
+//! each other's state. `ScopedTools` is the agent's view of the tool
+//! registry: only its allowlisted tools, each call charged against its
+//! `ResourceLimits` through a shared `UsageMeter`.
 
+use crate::agent::RegisteredTool;
 use crate::config::SafetyConfig;
+use crate::error::ToolError;
 use crate::memory::MemorySystem;
 use crate::safety::SafetyGuardian;
+use crate::types::{ToolDefinition, ToolOutput};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 /// Resource limits for an agent.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+///
+/// Tool calls and runtime accumulate over the agent's lifetime; tokens are
+/// counted per task.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ResourceLimits {
     pub max_memory_mb: Option<u64>,
     pub max_tokens_per_turn: Option<u64>,
@@ -21,6 +33,275 @@ pub struct ResourceLimits {
     pub max_runtime_secs: Option<u64>,
 }
 
+/// Which limit a [`LimitViolation`] broke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitKind {
+    /// A tool outside the agent's allowlist. The call is refused, but the
+    /// agent keeps running.
+    ToolNotPermitted,
+    ToolCalls,
+    Tokens,
+    Runtime,
+}
+
+impl LimitKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LimitKind::ToolNotPermitted => "tool_not_permitted",
+            LimitKind::ToolCalls => "max_tool_calls",
+            LimitKind::Tokens => "max_tokens_per_turn",
+            LimitKind::Runtime => "max_runtime_secs",
+        }
+    }
+
+    /// Whether the agent is terminated for this violation.
+    pub fn terminates(&self) -> bool {
+        !matches!(self, LimitKind::ToolNotPermitted)
+    }
+}
+
+/// A refused tool call or exceeded limit, recorded for the audit trail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitViolation {
+    pub kind: LimitKind,
+    pub tool: Option<String>,
+    pub detail: String,
+}
+
+/// Resources an agent has consumed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    pub tool_calls: u32,
+    /// Tokens used by the current (or last) task.
+    pub task_tokens: u64,
+    pub total_tokens: u64,
+    /// Wall-clock time spent running tasks.
+    pub runtime_ms: u64,
+    pub violations: u32,
+}
+
+/// Counts an agent's tool calls, tokens and task runtime. Shared between
+/// the agent's context and the tools handed to its task handler.
+#[derive(Debug, Default)]
+pub struct UsageMeter {
+    tool_calls: AtomicU32,
+    task_tokens: AtomicU64,
+    total_tokens: AtomicU64,
+    runtime_ms: AtomicU64,
+    violation_count: AtomicU32,
+    task_started: Mutex<Option<Instant>>,
+    violations: Mutex<Vec<LimitViolation>>,
+}
+
+impl UsageMeter {
+    pub fn usage(&self) -> ResourceUsage {
+        ResourceUsage {
+            tool_calls: self.tool_calls(),
+            task_tokens: self.task_tokens.load(Ordering::SeqCst),
+            total_tokens: self.total_tokens.load(Ordering::SeqCst),
+            runtime_ms: self.runtime().as_millis() as u64,
+            violations: self.violation_count.load(Ordering::SeqCst),
+        }
+    }
+
+    pub fn tool_calls(&self) -> u32 {
+        self.tool_calls.load(Ordering::SeqCst)
+    }
+
+    /// Start timing a task and reset its token count.
+    pub fn begin_task(&self) {
+        self.task_tokens.store(0, Ordering::SeqCst);
+        *self.task_started.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Stop timing the current task, adding its duration to the runtime.
+    pub fn end_task(&self) {
+        if let Some(started) = self.task_started.lock().unwrap().take() {
+            self.runtime_ms
+                .fetch_add(started.elapsed().as_millis() as u64, Ordering::SeqCst);
+        }
+    }
+
+    /// Runtime so far, including the task in progress.
+    pub fn runtime(&self) -> Duration {
+        let running = self
+            .task_started
+            .lock()
+            .unwrap()
+            .map(|started| started.elapsed())
+            .unwrap_or_default();
+        Duration::from_millis(self.runtime_ms.load(Ordering::SeqCst)) + running
+    }
+
+    /// Runtime left under `max_runtime_secs`, if set.
+    pub fn remaining_runtime(&self, limits: &ResourceLimits) -> Option<Duration> {
+        limits
+            .max_runtime_secs
+            .map(|max| Duration::from_secs(max).saturating_sub(self.runtime()))
+    }
+
+    /// Count one tool call, or record a violation if `max_tool_calls` is used up.
+    pub fn charge_tool_call(
+        &self,
+        limits: &ResourceLimits,
+        tool: &str,
+    ) -> Result<(), LimitViolation> {
+        let calls = self.tool_calls.fetch_add(1, Ordering::SeqCst);
+        if let Some(max) = limits.max_tool_calls
+            && calls >= max
+        {
+            self.tool_calls.fetch_sub(1, Ordering::SeqCst);
+            return Err(self.record(LimitViolation {
+                kind: LimitKind::ToolCalls,
+                tool: Some(tool.to_string()),
+                detail: format!("max_tool_calls limit reached ({}/{})", calls, max),
+            }));
+        }
+        Ok(())
+    }
+
+    /// Add tokens used by the current task, or record a violation when they
+    /// pass `max_tokens_per_turn`.
+    pub fn charge_tokens(
+        &self,
+        limits: &ResourceLimits,
+        tokens: u64,
+    ) -> Result<(), LimitViolation> {
+        let used = self.task_tokens.fetch_add(tokens, Ordering::SeqCst) + tokens;
+        self.total_tokens.fetch_add(tokens, Ordering::SeqCst);
+        if let Some(max) = limits.max_tokens_per_turn
+            && used > max
+        {
+            return Err(self.record(LimitViolation {
+                kind: LimitKind::Tokens,
+                tool: None,
+                detail: format!("max_tokens_per_turn exceeded ({}/{})", used, max),
+            }));
+        }
+        Ok(())
+    }
+
+    /// Queue a violation for the audit trail, returning it.
+    pub fn record(&self, violation: LimitViolation) -> LimitViolation {
+        self.violation_count.fetch_add(1, Ordering::SeqCst);
+        self.violations.lock().unwrap().push(violation.clone());
+        violation
+    }
+
+    /// Violations recorded since the last call.
+    pub fn take_violations(&self) -> Vec<LimitViolation> {
+        std::mem::take(&mut *self.violations.lock().unwrap())
+    }
+
+    /// Reset the tool call count (e.g., at the start of a new turn).
+    pub fn reset_tool_calls(&self) {
+        self.tool_calls.store(0, Ordering::SeqCst);
+    }
+}
+
+/// An agent's view of the tool registry: only its allowlisted tools, with
+/// every call checked against its resource limits.
+#[derive(Clone)]
+pub struct ScopedTools {
+    tools: HashMap<String, Arc<RegisteredTool>>,
+    limits: ResourceLimits,
+    usage: Arc<UsageMeter>,
+}
+
+impl ScopedTools {
+    /// Filter `tools` down to `allowlist` (`None` keeps them all).
+    pub fn new(
+        tools: &HashMap<String, Arc<RegisteredTool>>,
+        allowlist: Option<&BTreeSet<String>>,
+        limits: ResourceLimits,
+        usage: Arc<UsageMeter>,
+    ) -> Self {
+        let tools = tools
+            .iter()
+            .filter(|(name, _)| allowlist.is_none_or(|allowed| allowed.contains(*name)))
+            .map(|(name, tool)| (name.clone(), Arc::clone(tool)))
+            .collect();
+        Self {
+            tools,
+            limits,
+            usage,
+        }
+    }
+
+    /// Names of the tools this agent may call, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.tools.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.tools.contains_key(name)
+    }
+
+    /// Definitions to offer the model.
+    pub fn definitions(&self) -> Vec<ToolDefinition> {
+        let mut defs: Vec<ToolDefinition> = self
+            .tools
+            .values()
+            .map(|tool| tool.definition.clone())
+            .collect();
+        defs.sort_by(|a, b| a.name.cmp(&b.name));
+        defs
+    }
+
+    /// Run a tool on the agent's behalf. Tools outside the allowlist are
+    /// refused even when the model asks for them, and a call is cut off when
+    /// the agent's runtime runs out.
+    pub async fn execute(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> Result<ToolOutput, ToolError> {
+        let Some(tool) = self.tools.get(name) else {
+            let violation = self.usage.record(LimitViolation {
+                kind: LimitKind::ToolNotPermitted,
+                tool: Some(name.to_string()),
+                detail: format!("'{}' is not in this agent's tool allowlist", name),
+            });
+            return Err(ToolError::PermissionDenied {
+                name: name.to_string(),
+                reason: violation.detail,
+            });
+        };
+        self.usage
+            .charge_tool_call(&self.limits, name)
+            .map_err(|violation| ToolError::PermissionDenied {
+                name: name.to_string(),
+                reason: violation.detail,
+            })?;
+
+        let call = (tool.executor)(arguments);
+        let Some(remaining) = self.usage.remaining_runtime(&self.limits) else {
+            return call.await;
+        };
+        match tokio::time::timeout(remaining, call).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.usage.record(LimitViolation {
+                    kind: LimitKind::Runtime,
+                    tool: Some(name.to_string()),
+                    detail: format!(
+                        "max_runtime_secs reached ({}s) during '{}'",
+                        self.limits.max_runtime_secs.unwrap_or(0),
+                        name
+                    ),
+                });
+                Err(ToolError::Timeout {
+                    name: name.to_string(),
+                    timeout_secs: remaining.as_secs(),
+                })
+            }
+        }
+    }
+}
+
 /// Status of an agent.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AgentStatus {
@@ -48,6 +329,12 @@ pub struct AgentContext {
     pub llm_override: Option<String>,
     /// Per-agent resource constraints.
     pub resource_limits: ResourceLimits,
+    /// Role the agent was spawned with, if any.
+    pub role: Option<String>,
+    /// Tools this agent may call. `None` allows every registered tool.
+    pub tool_allowlist: Option<BTreeSet<String>>,
+    /// Resources consumed so far, shared with the agent's scoped tools.
+    pub usage: Arc<UsageMeter>,
     /// When this agent was created.
     pub created_at: DateTime<Utc>,
     /// Current status.
@@ -66,6 +353,9 @@ impl AgentContext {
             workspace_dir: None,
             llm_override: None,
             resource_limits: ResourceLimits::default(),
+            role: None,
+            tool_allowlist: None,
+            usage: Arc::new(UsageMeter::default()),
             created_at: Utc::now(),
             status: AgentStatus::Idle,
         }
@@ -87,6 +377,9 @@ impl AgentContext {
             workspace_dir: None,
             llm_override: None,
             resource_limits: ResourceLimits::default(),
+            role: None,
+            tool_allowlist: None,
+            usage: Arc::new(UsageMeter::default()),
             created_at: Utc::now(),
             status: AgentStatus::Idle,
         }
@@ -96,6 +389,23 @@ impl AgentContext {
     pub fn is_child(&self) -> bool {
         self.parent_id.is_some()
     }
+
+    /// Whether this agent may call `tool`.
+    pub fn permits_tool(&self, tool: &str) -> bool {
+        self.tool_allowlist
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(tool))
+    }
+
+    /// This agent's view of `tools`.
+    pub fn scoped_tools(&self, tools: &HashMap<String, Arc<RegisteredTool>>) -> ScopedTools {
+        ScopedTools::new(
+            tools,
+            self.tool_allowlist.as_ref(),
+            self.resource_limits.clone(),
+            Arc::clone(&self.usage),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -180,6 +490,39 @@ mod tests {
         assert_eq!(limits.max_tool_calls, Some(50));
     }
 
+    #[test]
+    fn test_usage_meter_enforces_limits() {
+        let meter = UsageMeter::default();
+        let limits = ResourceLimits {
+            max_tokens_per_turn: Some(100),
+            max_tool_calls: Some(1),
+            ..Default::default()
+        };
+        assert!(meter.charge_tool_call(&limits, "web_fetch").is_ok());
+        let refused = meter.charge_tool_call(&limits, "web_fetch").unwrap_err();
+        assert_eq!(refused.kind, LimitKind::ToolCalls);
+        assert_eq!(meter.tool_calls(), 1, "refused calls are not counted");
+
+        meter.begin_task();
+        assert!(meter.charge_tokens(&limits, 80).is_ok());
+        assert_eq!(
+            meter.charge_tokens(&limits, 30).unwrap_err().kind,
+            LimitKind::Tokens
+        );
+        meter.end_task();
+        meter.begin_task();
+        assert!(
+            meter.charge_tokens(&limits, 80).is_ok(),
+            "tokens are per task"
+        );
+
+        let usage = meter.usage();
+        assert_eq!(usage.total_tokens, 190);
+        assert_eq!(usage.violations, 2);
+        assert_eq!(meter.take_violations().len(), 2);
+        assert!(meter.take_violations().is_empty());
+    }
+
     #[test]
     fn test_agent_status_transitions() {
         let mut ctx = AgentContext::new("test", 10, SafetyConfig::default());
@@ -2,12 +2,23 @@
 //!
 //! Provides an in-process message bus for agents to communicate asynchronously.
 //! Each agent has a mailbox (bounded queue) to prevent memory exhaustion.
+//!
+//! Receiving an envelope leases it rather than removing it: the receiver must
+//! [`MessageBus::ack`] it, or it is redelivered once the visibility timeout
+//! passes. Envelopes that outlive their TTL or exhaust their delivery
+//! attempts move to a dead-letter queue. A bus opened with
+//! [`MessageBus::persistent`] writes its queues to disk after every change,
+//! so in-flight messages survive a restart.
 
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use uuid::Uuid;
 
 /// Payload types for inter-agent communication.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AgentPayload {
     /// Request to execute a task.
     TaskRequest {
@@ -50,7 +61,7 @@ pub enum AgentPayload {
 }
 
 /// Priority levels for inter-agent messages.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum MessagePriority {
     Low = 0,
     Normal = 1,
@@ -59,7 +70,7 @@ pub enum MessagePriority {
 }
 
 /// An envelope wrapping a payload with routing information.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentEnvelope {
     /// Unique message ID.
     pub id: Uuid,
@@ -75,6 +86,13 @@ pub struct AgentEnvelope {
     pub correlation_id: Option<Uuid>,
     /// Message priority.
     pub priority: MessagePriority,
+    /// How long after creation the envelope may still be delivered. Expired
+    /// envelopes move to the dead-letter queue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<Duration>,
+    /// Number of times the envelope has been received (leased).
+    #[serde(default)]
+    pub delivery_attempts: u32,
 }
 
 impl AgentEnvelope {
@@ -88,6 +106,8 @@ impl AgentEnvelope {
             created_at: chrono::Utc::now(),
             correlation_id: None,
             priority: MessagePriority::Normal,
+            ttl: None,
+            delivery_attempts: 0,
         }
     }
 
@@ -102,89 +122,436 @@ impl AgentEnvelope {
         self.priority = priority;
         self
     }
+
+    /// Set a time-to-live, measured from creation.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Whether the envelope's TTL has elapsed at `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.ttl.is_some_and(|ttl| {
+            chrono::Duration::from_std(ttl).is_ok_and(|ttl| now - self.created_at >= ttl)
+        })
+    }
+}
+
+/// Why an envelope was moved to the dead-letter queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadLetterReason {
+    /// Its TTL elapsed before it was acknowledged.
+    Expired,
+    /// It was received `max_delivery_attempts` times without an ack.
+    RetriesExhausted,
+    /// Its recipient was unregistered while it was pending.
+    Unregistered,
+    /// A critical message needed its place in a full mailbox.
+    Displaced,
+}
+
+/// An undeliverable envelope kept for inspection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub envelope: AgentEnvelope,
+    pub reason: DeadLetterReason,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+/// Lease, retry and TTL settings for a [`MessageBus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeliveryPolicy {
+    /// How long a received envelope waits for an ack before redelivery.
+    pub visibility_timeout: Duration,
+    /// Receives allowed per envelope before it is dead-lettered.
+    pub max_delivery_attempts: u32,
+    /// TTL applied to envelopes sent without one.
+    pub default_ttl: Option<Duration>,
+    /// Dead letters kept; the oldest are discarded beyond this.
+    pub max_dead_letters: usize,
+}
+
+impl Default for DeliveryPolicy {
+    fn default() -> Self {
+        Self {
+            visibility_timeout: Duration::from_secs(30),
+            max_delivery_attempts: 3,
+            default_ttl: None,
+            max_dead_letters: 1000,
+        }
+    }
+}
+
+impl DeliveryPolicy {
+    /// The policy described by the `[multi_agent]` config section.
+    pub fn from_config(config: &crate::config::MultiAgentConfig) -> Self {
+        Self {
+            visibility_timeout: Duration::from_secs(config.visibility_timeout_secs),
+            max_delivery_attempts: config.max_delivery_attempts.max(1),
+            default_ttl: config.message_ttl_secs.map(Duration::from_secs),
+            ..Self::default()
+        }
+    }
+}
+
+/// Envelopes moved by a [`MessageBus::sweep`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SweepReport {
+    /// Unacknowledged envelopes returned to their mailbox.
+    pub redelivered: usize,
+    /// Envelopes moved to the dead-letter queue.
+    pub dead_lettered: usize,
+}
+
+/// A received envelope awaiting acknowledgment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Lease {
+    envelope: AgentEnvelope,
+    expires_at: DateTime<Utc>,
+}
+
+/// Everything a persistent bus writes to disk.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BusState {
+    mailboxes: HashMap<Uuid, Vec<AgentEnvelope>>,
+    /// Leased envelopes keyed by envelope ID.
+    leases: HashMap<Uuid, Lease>,
+    dead_letters: VecDeque<DeadLetter>,
 }
 
 /// In-process message bus for inter-agent communication.
 /// Messages are stored in priority order (highest priority first, FIFO within same priority).
 pub struct MessageBus {
-    mailboxes: HashMap<Uuid, Vec<AgentEnvelope>>,
+    state: BusState,
     max_mailbox_size: usize,
+    policy: DeliveryPolicy,
+    persist_path: Option<PathBuf>,
 }
 
 impl MessageBus {
     /// Create a new message bus with a maximum mailbox size per agent.
     pub fn new(max_mailbox_size: usize) -> Self {
         Self {
-            mailboxes: HashMap::new(),
+            state: BusState::default(),
             max_mailbox_size,
+            policy: DeliveryPolicy::default(),
+            persist_path: None,
         }
     }
 
+    /// Open a bus that persists its mailboxes, leases and dead letters to
+    /// `path`, restoring any state a previous instance left there. Leases
+    /// held by a crashed instance are redelivered once they time out.
+    pub fn persistent(path: impl Into<PathBuf>, max_mailbox_size: usize) -> Result<Self, String> {
+        let path = path.into();
+        let state = if path.exists() {
+            let json = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Read message bus state {}: {}", path.display(), e))?;
+            serde_json::from_str(&json)
+                .map_err(|e| format!("Parse message bus state {}: {}", path.display(), e))?
+        } else {
+            BusState::default()
+        };
+        Ok(Self {
+            state,
+            max_mailbox_size,
+            policy: DeliveryPolicy::default(),
+            persist_path: Some(path),
+        })
+    }
+
+    /// Create a bus from the `[multi_agent]` config section, persistent when
+    /// `mailbox_state_path` is set.
+    pub fn from_config(config: &crate::config::MultiAgentConfig) -> Result<Self, String> {
+        let bus = match &config.mailbox_state_path {
+            Some(path) => Self::persistent(path, config.max_mailbox_size)?,
+            None => Self::new(config.max_mailbox_size),
+        };
+        Ok(bus.with_policy(DeliveryPolicy::from_config(config)))
+    }
+
+    /// Replace the lease, retry and TTL settings.
+    pub fn with_policy(mut self, policy: DeliveryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The current lease, retry and TTL settings.
+    pub fn policy(&self) -> &DeliveryPolicy {
+        &self.policy
+    }
+
     /// Register a mailbox for an agent.
     pub fn register(&mut self, agent_id: Uuid) {
-        self.mailboxes.entry(agent_id).or_default();
+        self.state.mailboxes.entry(agent_id).or_default();
+        self.persist();
     }
 
-    /// Remove a mailbox for an agent, discarding pending messages.
+    /// Remove a mailbox for an agent. Its pending and leased messages move to
+    /// the dead-letter queue.
     pub fn unregister(&mut self, agent_id: &Uuid) {
-        self.mailboxes.remove(agent_id);
+        let now = Utc::now();
+        let pending = self.state.mailboxes.remove(agent_id).unwrap_or_default();
+        let leased: Vec<Uuid> = self
+            .state
+            .leases
+            .iter()
+            .filter(|(_, lease)| lease.envelope.to == *agent_id)
+            .map(|(id, _)| *id)
+            .collect();
+        for envelope in pending {
+            self.dead_letter(envelope, DeadLetterReason::Unregistered, now);
+        }
+        for id in leased {
+            if let Some(lease) = self.state.leases.remove(&id) {
+                self.dead_letter(lease.envelope, DeadLetterReason::Unregistered, now);
+            }
+        }
+        self.persist();
     }
 
     /// Send a message to an agent's mailbox. Returns Err if the mailbox is full
     /// or the recipient is not registered. Messages are inserted in priority order.
-    pub fn send(&mut self, envelope: AgentEnvelope) -> Result<(), String> {
+    ///
+    /// A critical message sent to a full mailbox displaces the newest
+    /// lower-priority message there, which is dead-lettered.
+    pub fn send(&mut self, mut envelope: AgentEnvelope) -> Result<(), String> {
+        let now = Utc::now();
+        self.sweep_at(now);
+        if envelope.ttl.is_none() {
+            envelope.ttl = self.policy.default_ttl;
+        }
+
+        let max = self.max_mailbox_size;
         let mailbox = self
+            .state
             .mailboxes
             .get_mut(&envelope.to)
             .ok_or_else(|| format!("Agent {} not registered", envelope.to))?;
 
-        if mailbox.len() >= self.max_mailbox_size {
-            return Err(format!(
-                "Mailbox for agent {} is full (max {})",
-                envelope.to, self.max_mailbox_size
-            ));
+        let mut displaced = None;
+        if mailbox.len() >= max {
+            let can_displace = envelope.priority == MessagePriority::Critical
+                && mailbox
+                    .last()
+                    .is_some_and(|e| e.priority < MessagePriority::Critical);
+            if !can_displace {
+                return Err(format!(
+                    "Mailbox for agent {} is full (max {})",
+                    envelope.to, max
+                ));
+            }
+            displaced = mailbox.pop();
         }
+        enqueue(mailbox, envelope);
 
-        // Insert in sorted position: higher priority first, FIFO within same priority.
-        // We find the first position where the existing message has lower priority.
-        let pos = mailbox
-            .iter()
-            .position(|e| e.priority < envelope.priority)
-            .unwrap_or(mailbox.len());
-        mailbox.insert(pos, envelope);
+        if let Some(victim) = displaced {
+            self.dead_letter(victim, DeadLetterReason::Displaced, now);
+        }
+        self.persist();
         Ok(())
     }
 
     /// Receive the highest-priority message from an agent's mailbox.
+    ///
+    /// The message is leased, not removed: call [`ack`](Self::ack) once it is
+    /// handled, or it is redelivered after the visibility timeout.
     pub fn receive(&mut self, agent_id: &Uuid) -> Option<AgentEnvelope> {
-        self.mailboxes.get_mut(agent_id).and_then(|mb| {
-            if mb.is_empty() {
-                None
+        let now = Utc::now();
+        self.sweep_at(now);
+        let mailbox = self.state.mailboxes.get_mut(agent_id)?;
+        if mailbox.is_empty() {
+            return None;
+        }
+        let mut envelope = mailbox.remove(0);
+        envelope.delivery_attempts += 1;
+        let expires_at = chrono::Duration::from_std(self.policy.visibility_timeout)
+            .ok()
+            .and_then(|timeout| now.checked_add_signed(timeout))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        self.state.leases.insert(
+            envelope.id,
+            Lease {
+                envelope: envelope.clone(),
+                expires_at,
+            },
+        );
+        self.persist();
+        Some(envelope)
+    }
+
+    /// Acknowledge a received message so it is never redelivered. Returns
+    /// false if it was not leased (already acked, redelivered or dead-lettered).
+    pub fn ack(&mut self, envelope_id: &Uuid) -> bool {
+        let acked = self.state.leases.remove(envelope_id).is_some();
+        if acked {
+            self.persist();
+        }
+        acked
+    }
+
+    /// Dead-letter expired messages and redeliver leases that timed out
+    /// without an ack. `send` and `receive` sweep automatically.
+    pub fn sweep(&mut self) -> SweepReport {
+        let report = self.sweep_at(Utc::now());
+        if report != SweepReport::default() {
+            self.persist();
+        }
+        report
+    }
+
+    fn sweep_at(&mut self, now: DateTime<Utc>) -> SweepReport {
+        let mut expired = Vec::new();
+        for mailbox in self.state.mailboxes.values_mut() {
+            let (dead, live): (Vec<_>, Vec<_>) = mailbox.drain(..).partition(|e| e.is_expired(now));
+            *mailbox = live;
+            expired.extend(dead);
+        }
+
+        let timed_out: Vec<Uuid> = self
+            .state
+            .leases
+            .iter()
+            .filter(|(_, lease)| lease.expires_at <= now)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut exhausted = Vec::new();
+        let mut orphaned = Vec::new();
+        let mut report = SweepReport::default();
+        for id in timed_out {
+            let Some(Lease { envelope, .. }) = self.state.leases.remove(&id) else {
+                continue;
+            };
+            if envelope.is_expired(now) {
+                expired.push(envelope);
+            } else if envelope.delivery_attempts >= self.policy.max_delivery_attempts {
+                exhausted.push(envelope);
+            } else if let Some(mailbox) = self.state.mailboxes.get_mut(&envelope.to) {
+                enqueue(mailbox, envelope);
+                report.redelivered += 1;
             } else {
-                Some(mb.remove(0))
+                orphaned.push(envelope);
             }
-        })
+        }
+
+        for (envelopes, reason) in [
+            (expired, DeadLetterReason::Expired),
+            (exhausted, DeadLetterReason::RetriesExhausted),
+            (orphaned, DeadLetterReason::Unregistered),
+        ] {
+            report.dead_lettered += envelopes.len();
+            for envelope in envelopes {
+                self.dead_letter(envelope, reason, now);
+            }
+        }
+        if report.dead_lettered > 0 {
+            tracing::warn!(
+                count = report.dead_lettered,
+                "Inter-agent messages moved to the dead-letter queue"
+            );
+        }
+        report
+    }
+
+    fn dead_letter(
+        &mut self,
+        envelope: AgentEnvelope,
+        reason: DeadLetterReason,
+        now: DateTime<Utc>,
+    ) {
+        self.state.dead_letters.push_back(DeadLetter {
+            envelope,
+            reason,
+            dead_lettered_at: now,
+        });
+        while self.state.dead_letters.len() > self.policy.max_dead_letters {
+            self.state.dead_letters.pop_front();
+        }
     }
 
     /// Peek at the highest-priority message without removing it.
     pub fn peek(&self, agent_id: &Uuid) -> Option<&AgentEnvelope> {
-        self.mailboxes.get(agent_id).and_then(|mb| mb.first())
+        self.state.mailboxes.get(agent_id).and_then(|mb| mb.first())
     }
 
     /// Number of pending messages for a specific agent.
     pub fn pending_count(&self, agent_id: &Uuid) -> usize {
-        self.mailboxes.get(agent_id).map_or(0, |mb| mb.len())
+        self.state.mailboxes.get(agent_id).map_or(0, |mb| mb.len())
     }
 
     /// Total pending messages across all mailboxes.
     pub fn pending_count_all(&self) -> usize {
-        self.mailboxes.values().map(|mb| mb.len()).sum()
+        self.state.mailboxes.values().map(|mb| mb.len()).sum()
+    }
+
+    /// Number of received messages awaiting acknowledgment.
+    pub fn in_flight_count(&self) -> usize {
+        self.state.leases.len()
     }
 
     /// Number of registered mailboxes.
     pub fn mailbox_count(&self) -> usize {
-        self.mailboxes.len()
+        self.state.mailboxes.len()
+    }
+
+    /// Pending messages per registered agent.
+    pub fn queue_depths(&self) -> HashMap<Uuid, usize> {
+        self.state
+            .mailboxes
+            .iter()
+            .map(|(id, mb)| (*id, mb.len()))
+            .collect()
+    }
+
+    /// Undeliverable messages, oldest first.
+    pub fn dead_letters(&self) -> impl Iterator<Item = &DeadLetter> {
+        self.state.dead_letters.iter()
     }
+
+    /// Number of messages in the dead-letter queue.
+    pub fn dead_letter_count(&self) -> usize {
+        self.state.dead_letters.len()
+    }
+
+    /// Remove and return every dead letter.
+    pub fn drain_dead_letters(&mut self) -> Vec<DeadLetter> {
+        let drained = self.state.dead_letters.drain(..).collect();
+        self.persist();
+        drained
+    }
+
+    /// Write the state to disk if the bus is persistent. Failures are logged
+    /// and delivery continues in memory.
+    fn persist(&self) {
+        let Some(path) = &self.persist_path else {
+            return;
+        };
+        if let Err(e) = write_state(path, &self.state) {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to persist message bus");
+        }
+    }
+}
+
+/// Insert in sorted position: higher priority first, FIFO within same priority.
+fn enqueue(mailbox: &mut Vec<AgentEnvelope>, envelope: AgentEnvelope) {
+    let pos = mailbox
+        .iter()
+        .position(|e| e.priority < envelope.priority)
+        .unwrap_or(mailbox.len());
+    mailbox.insert(pos, envelope);
+}
+
+/// Atomically replace the state file.
+fn write_state(path: &Path, state: &BusState) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Create state dir: {}", e))?;
+    }
+    let json = serde_json::to_string(state).map_err(|e| format!("Serialize state: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| format!("Write state: {}", e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Rename state: {}", e))?;
+    Ok(())
 }
 
 impl Default for MessageBus {
@@ -552,4 +919,192 @@ mod tests {
         let peeked = bus.peek(&b).unwrap();
         assert_eq!(peeked.priority, MessagePriority::Critical);
     }
+
+    // --- Leases, TTL, dead letters and persistence ---
+
+    fn immediate_redelivery(max_delivery_attempts: u32) -> DeliveryPolicy {
+        DeliveryPolicy {
+            visibility_timeout: Duration::ZERO,
+            max_delivery_attempts,
+            ..DeliveryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_unacked_message_redelivers_then_dead_letters() {
+        let mut bus = MessageBus::new(10).with_policy(immediate_redelivery(2));
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        bus.register(b);
+        let envelope = AgentEnvelope::new(a, b, AgentPayload::StatusQuery);
+        let id = envelope.id;
+        bus.send(envelope).unwrap();
+
+        let first = bus.receive(&b).unwrap();
+        assert_eq!((first.id, first.delivery_attempts), (id, 1));
+        assert_eq!(bus.in_flight_count(), 1);
+
+        let second = bus.receive(&b).unwrap();
+        assert_eq!((second.id, second.delivery_attempts), (id, 2));
+
+        assert!(bus.receive(&b).is_none());
+        assert_eq!(bus.in_flight_count(), 0);
+        let dead: Vec<_> = bus.dead_letters().collect();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].envelope.id, id);
+        assert_eq!(dead[0].reason, DeadLetterReason::RetriesExhausted);
+    }
+
+    #[test]
+    fn test_ack_prevents_redelivery() {
+        let mut bus = MessageBus::new(10).with_policy(immediate_redelivery(3));
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        bus.register(b);
+        bus.send(AgentEnvelope::new(a, b, AgentPayload::StatusQuery))
+            .unwrap();
+
+        let received = bus.receive(&b).unwrap();
+        assert!(bus.ack(&received.id));
+        assert!(!bus.ack(&received.id));
+        assert!(bus.receive(&b).is_none());
+        assert_eq!(bus.sweep(), SweepReport::default());
+        assert_eq!(bus.dead_letter_count(), 0);
+    }
+
+    #[test]
+    fn test_expired_messages_move_to_dead_letters() {
+        let mut bus = MessageBus::new(10).with_policy(DeliveryPolicy {
+            default_ttl: Some(Duration::from_secs(3600)),
+            ..DeliveryPolicy::default()
+        });
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        bus.register(b);
+        bus.send(AgentEnvelope::new(a, b, AgentPayload::StatusQuery).with_ttl(Duration::ZERO))
+            .unwrap();
+        bus.send(AgentEnvelope::new(a, b, AgentPayload::Shutdown))
+            .unwrap();
+        assert_eq!(bus.peek(&b).unwrap().ttl, Some(Duration::from_secs(3600)));
+
+        // The second send swept the expired message out of the mailbox.
+        assert_eq!(bus.pending_count(&b), 1);
+        assert_eq!(bus.dead_letter_count(), 1);
+        assert!(matches!(
+            bus.receive(&b).unwrap().payload,
+            AgentPayload::Shutdown
+        ));
+        let dead = bus.drain_dead_letters();
+        assert_eq!(dead[0].reason, DeadLetterReason::Expired);
+        assert_eq!(bus.dead_letter_count(), 0);
+    }
+
+    #[test]
+    fn test_critical_message_displaces_bulk_in_full_mailbox() {
+        let mut bus = MessageBus::new(2);
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        bus.register(b);
+        let bulk =
+            AgentEnvelope::new(a, b, AgentPayload::StatusQuery).with_priority(MessagePriority::Low);
+        let bulk_id = bulk.id;
+        bus.send(bulk).unwrap();
+        bus.send(AgentEnvelope::new(a, b, AgentPayload::StatusQuery))
+            .unwrap();
+        assert!(
+            bus.send(AgentEnvelope::new(a, b, AgentPayload::StatusQuery))
+                .is_err()
+        );
+
+        bus.send(
+            AgentEnvelope::new(a, b, AgentPayload::Shutdown)
+                .with_priority(MessagePriority::Critical),
+        )
+        .unwrap();
+        assert_eq!(bus.receive(&b).unwrap().priority, MessagePriority::Critical);
+        assert_eq!(bus.receive(&b).unwrap().priority, MessagePriority::Normal);
+        let dead: Vec<_> = bus.dead_letters().collect();
+        assert_eq!(dead[0].envelope.id, bulk_id);
+        assert_eq!(dead[0].reason, DeadLetterReason::Displaced);
+
+        // A mailbox full of critical messages refuses another.
+        let mut bus = MessageBus::new(1);
+        bus.register(b);
+        let critical = || {
+            AgentEnvelope::new(a, b, AgentPayload::Shutdown)
+                .with_priority(MessagePriority::Critical)
+        };
+        bus.send(critical()).unwrap();
+        assert!(bus.send(critical()).is_err());
+    }
+
+    #[test]
+    fn test_unregister_dead_letters_pending_and_leased() {
+        let mut bus = MessageBus::new(10);
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        bus.register(b);
+        bus.send(AgentEnvelope::new(a, b, AgentPayload::StatusQuery))
+            .unwrap();
+        bus.send(AgentEnvelope::new(a, b, AgentPayload::Shutdown))
+            .unwrap();
+        bus.receive(&b).unwrap();
+
+        bus.unregister(&b);
+        assert_eq!(bus.in_flight_count(), 0);
+        assert_eq!(bus.dead_letter_count(), 2);
+        assert!(
+            bus.dead_letters()
+                .all(|d| d.reason == DeadLetterReason::Unregistered)
+        );
+    }
+
+    #[test]
+    fn test_redelivery_after_crash_with_persisted_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mailboxes.json");
+        let (coordinator, worker) = (Uuid::new_v4(), Uuid::new_v4());
+        let task = AgentEnvelope::new(
+            coordinator,
+            worker,
+            AgentPayload::TaskRequest {
+                description: "index repo".into(),
+                args: HashMap::new(),
+            },
+        )
+        .with_priority(MessagePriority::High);
+        let task_id = task.id;
+
+        {
+            let mut bus = MessageBus::persistent(&path, 10).unwrap();
+            bus.register(worker);
+            bus.send(task).unwrap();
+            bus.send(AgentEnvelope::new(
+                coordinator,
+                worker,
+                AgentPayload::StatusQuery,
+            ))
+            .unwrap();
+            // The worker takes the task, then the process dies before it acks.
+            assert_eq!(bus.receive(&worker).unwrap().id, task_id);
+        }
+
+        let mut bus = MessageBus::persistent(&path, 10).unwrap();
+        assert_eq!(bus.pending_count(&worker), 1);
+        assert_eq!(bus.in_flight_count(), 1);
+
+        // Still leased until the crashed instance's visibility timeout passes.
+        assert_eq!(bus.sweep(), SweepReport::default());
+        let after_timeout = Utc::now() + chrono::Duration::seconds(31);
+        assert_eq!(bus.sweep_at(after_timeout).redelivered, 1);
+
+        let redelivered = bus.receive(&worker).unwrap();
+        assert_eq!(redelivered.id, task_id);
+        assert_eq!(redelivered.delivery_attempts, 2);
+        assert!(matches!(
+            redelivered.payload,
+            AgentPayload::TaskRequest { ref description, .. } if description == "index repo"
+        ));
+        assert!(bus.ack(&task_id));
+
+        let bus = MessageBus::persistent(&path, 10).unwrap();
+        assert_eq!(bus.in_flight_count(), 0);
+        assert_eq!(bus.pending_count(&worker), 1);
+        assert_eq!(bus.dead_letter_count(), 0);
+    }
 }
@@ -1,7 +1,8 @@
 //! Multi-agent system — isolation, routing, spawning, and inter-agent messaging.
 //!
 //! Provides the building blocks for running multiple agents within a single
-//! Rustant instance, each with its own isolated memory and safety context.
+//! Rustant instance, each with its own isolated memory, safety context and
+//! tool allowlist.
 
 pub mod isolation;
 pub mod messaging;
@@ -9,11 +10,19 @@ pub mod orchestrator;
 pub mod routing;
 pub mod spawner;
 
-pub use isolation::{AgentContext, AgentStatus, ResourceLimits};
-pub use messaging::{AgentEnvelope, AgentPayload, MessageBus, MessagePriority};
-pub use orchestrator::{AgentOrchestrator, TaskHandler};
+pub use isolation::{
+    AgentContext, AgentStatus, LimitKind, LimitViolation, ResourceLimits, ResourceUsage,
+    ScopedTools, UsageMeter,
+};
+pub use messaging::{
+    AgentEnvelope, AgentPayload, DeadLetter, DeadLetterReason, DeliveryPolicy, MessageBus,
+    MessagePriority, SweepReport,
+};
+pub use orchestrator::{
+    AgentOrchestrator, AgentUsage, MemoryMerge, OrchestratorStatus, TaskHandler, TaskScope,
+};
 pub use routing::{AgentRoute, AgentRouter};
-pub use spawner::AgentSpawner;
+pub use spawner::{AgentSpawner, SpawnRequest, SpawnerConfig};
 
 #[cfg(test)]
 mod tests {
@@ -3,15 +3,130 @@
 //!
 //! The orchestrator receives messages from the `MessageBus`, dispatches them
 //! to registered `TaskHandler` implementations, and returns results via the bus.
-//! It enforces `ResourceLimits` on each agent.
-
-use super::messaging::{AgentEnvelope, AgentPayload, MessageBus, MessagePriority};
+//! It enforces `ResourceLimits` on each agent: a handler only reaches tools
+//! through its agent's [`ScopedTools`], and an agent that runs out of tool
+//! calls, tokens or runtime is terminated with its partial result returned.
+//! Facts a child agent learns stay in its own memory until the parent
+//! accepts a [`MemoryMerge`].
+
+use super::isolation::{
+    AgentStatus, LimitKind, LimitViolation, ResourceLimits, ResourceUsage, ScopedTools, UsageMeter,
+};
+use super::messaging::{AgentEnvelope, AgentPayload, DeadLetter, MessageBus, MessagePriority};
 use super::routing::AgentRouter;
 use super::spawner::AgentSpawner;
+use crate::error::LlmError;
+use crate::memory::Fact;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Snapshot of the orchestrator's agents and message queues.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrchestratorStatus {
+    /// Agents known to the spawner.
+    pub agents: usize,
+    /// Agents with a registered task handler.
+    pub handlers: usize,
+    /// Messages waiting in mailboxes.
+    pub queued: usize,
+    /// Messages received but not yet acknowledged.
+    pub in_flight: usize,
+    /// Messages in the dead-letter queue.
+    pub dead_letters: usize,
+    /// Pending messages per agent mailbox.
+    pub queue_depths: HashMap<Uuid, usize>,
+    /// Consumption against limits per agent.
+    pub usage: HashMap<Uuid, AgentUsage>,
+    /// Memory merges waiting for review.
+    pub pending_merges: usize,
+}
+
+/// One agent's resource consumption against its limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentUsage {
+    pub name: String,
+    pub status: AgentStatus,
+    pub usage: ResourceUsage,
+    pub limits: ResourceLimits,
+}
+
+impl AgentUsage {
+    /// One-line summary, e.g. `researcher: 3/5 tool calls, 1200 tokens this
+    /// task (1800 total), 4s/60s runtime`.
+    pub fn summary(&self) -> String {
+        let of = |max: Option<u64>, unit: &str| {
+            max.map(|m| format!("/{}{}", m, unit)).unwrap_or_default()
+        };
+        let mut out = format!(
+            "{}: {}{} tool calls, {}{} tokens this task ({} total), {}s{} runtime",
+            self.name,
+            self.usage.tool_calls,
+            of(self.limits.max_tool_calls.map(u64::from), ""),
+            self.usage.task_tokens,
+            of(self.limits.max_tokens_per_turn, ""),
+            self.usage.total_tokens,
+            self.usage.runtime_ms / 1000,
+            of(self.limits.max_runtime_secs, "s"),
+        );
+        if self.usage.violations > 0 {
+            out.push_str(&format!(", {} violation(s)", self.usage.violations));
+        }
+        if self.status == AgentStatus::Terminated {
+            out.push_str(", terminated");
+        }
+        out
+    }
+}
+
+/// Facts a child agent learned during a task, held until the parent
+/// accepts or rejects them.
+#[derive(Debug, Clone)]
+pub struct MemoryMerge {
+    pub id: Uuid,
+    pub agent_id: Uuid,
+    pub parent_id: Uuid,
+    pub facts: Vec<Fact>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a handler gets for one task: the agent's scoped tools, a token
+/// meter, and a place for facts learned and partial output.
+pub struct TaskScope {
+    pub agent_id: Uuid,
+    pub tools: ScopedTools,
+    limits: ResourceLimits,
+    usage: Arc<UsageMeter>,
+    facts: Vec<Fact>,
+    partial: String,
+}
+
+impl TaskScope {
+    /// Count tokens the task used. An error means `max_tokens_per_turn` is
+    /// exhausted: the handler should stop, and the agent is terminated.
+    pub fn record_tokens(&self, tokens: u64) -> Result<(), String> {
+        self.usage
+            .charge_tokens(&self.limits, tokens)
+            .map_err(|violation| violation.detail)
+    }
+
+    /// Record a fact in the agent's own memory. It reaches the parent only
+    /// through an accepted [`MemoryMerge`].
+    pub fn remember(&mut self, fact: Fact) {
+        self.facts.push(fact);
+    }
+
+    /// Append output produced so far, returned if the task is cut short.
+    pub fn progress(&mut self, text: &str) {
+        if !self.partial.is_empty() {
+            self.partial.push('\n');
+        }
+        self.partial.push_str(text);
+    }
+}
+
 /// Trait for handling tasks dispatched by the orchestrator.
 ///
 /// Implementations receive a task description and arguments, execute the work,
@@ -23,6 +138,21 @@ pub trait TaskHandler: Send + Sync {
         description: &str,
         args: &HashMap<String, String>,
     ) -> Result<String, String>;
+
+    /// Handle a task with the agent's scoped tools and meters. Handlers that
+    /// call tools or an LLM should override this; the default runs
+    /// [`handle_task`](Self::handle_task) and counts the task as one tool call.
+    async fn handle_scoped_task(
+        &self,
+        description: &str,
+        args: &HashMap<String, String>,
+        scope: &mut TaskScope,
+    ) -> Result<String, String> {
+        if let Err(violation) = scope.usage.charge_tool_call(&scope.limits, "task") {
+            return Err(violation.detail);
+        }
+        self.handle_task(description, args).await
+    }
 }
 
 /// The agent orchestrator ties together the spawner, message bus, router,
@@ -32,7 +162,7 @@ pub struct AgentOrchestrator {
     bus: MessageBus,
     router: AgentRouter,
     handlers: HashMap<Uuid, Box<dyn TaskHandler>>,
-    tool_call_counts: HashMap<Uuid, u32>,
+    merges: Vec<MemoryMerge>,
 }
 
 impl AgentOrchestrator {
@@ -43,7 +173,7 @@ impl AgentOrchestrator {
             bus,
             router,
             handlers: HashMap::new(),
-            tool_call_counts: HashMap::new(),
+            merges: Vec::new(),
         }
     }
 
@@ -82,30 +212,119 @@ impl AgentOrchestrator {
         &mut self.router
     }
 
+    /// Agent, queue-depth, dead-letter and per-agent usage counts.
+    pub fn status(&self) -> OrchestratorStatus {
+        let usage = self
+            .spawner
+            .agent_ids()
+            .into_iter()
+            .filter_map(|id| self.agent_usage(&id).map(|usage| (id, usage)))
+            .collect();
+        OrchestratorStatus {
+            agents: self.spawner.agent_count(),
+            handlers: self.handlers.len(),
+            queued: self.bus.pending_count_all(),
+            in_flight: self.bus.in_flight_count(),
+            dead_letters: self.bus.dead_letter_count(),
+            queue_depths: self.bus.queue_depths(),
+            usage,
+            pending_merges: self.merges.len(),
+        }
+    }
+
+    /// An agent's consumption against its limits.
+    pub fn agent_usage(&self, agent_id: &Uuid) -> Option<AgentUsage> {
+        self.spawner.get(agent_id).map(|ctx| AgentUsage {
+            name: ctx.name.clone(),
+            status: ctx.status,
+            usage: ctx.usage.usage(),
+            limits: ctx.resource_limits.clone(),
+        })
+    }
+
+    /// Messages that could not be delivered, oldest first.
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.bus.dead_letters().cloned().collect()
+    }
+
     /// Get the current tool call count for an agent.
     pub fn tool_call_count(&self, agent_id: &Uuid) -> u32 {
-        self.tool_call_counts.get(agent_id).copied().unwrap_or(0)
+        self.spawner
+            .get(agent_id)
+            .map(|ctx| ctx.usage.tool_calls())
+            .unwrap_or(0)
     }
 
     /// Reset tool call counts for an agent (e.g., at the start of a new turn).
     pub fn reset_tool_counts(&mut self, agent_id: &Uuid) {
-        self.tool_call_counts.insert(*agent_id, 0);
+        if let Some(ctx) = self.spawner.get(agent_id) {
+            ctx.usage.reset_tool_calls();
+        }
+    }
+
+    /// Memory merges waiting for review, oldest first.
+    pub fn pending_merges(&self) -> &[MemoryMerge] {
+        &self.merges
+    }
+
+    /// Copy a merge's facts into the parent's memory. `accepted` selects
+    /// facts by ID; `None` accepts them all. Returns the number merged, which
+    /// leaves out facts the parent already knew.
+    pub fn apply_merge(
+        &mut self,
+        merge_id: Uuid,
+        accepted: Option<&[Uuid]>,
+    ) -> Result<usize, String> {
+        let index = self
+            .merges
+            .iter()
+            .position(|m| m.id == merge_id)
+            .ok_or_else(|| format!("Memory merge {} not found", merge_id))?;
+        let parent_id = self.merges[index].parent_id;
+        let parent = self
+            .spawner
+            .get_mut(&parent_id)
+            .ok_or_else(|| format!("Parent agent {} not found", parent_id))?;
+        let merge = self.merges.remove(index);
+        let mut merged = 0;
+        for mut fact in merge.facts {
+            if accepted.is_some_and(|ids| !ids.contains(&fact.id)) {
+                continue;
+            }
+            fact.tags.push(format!("agent:{}", merge.agent_id));
+            if parent.memory.add_fact(fact).is_none() {
+                merged += 1;
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Discard a merge; the facts stay in the child's memory only.
+    pub fn reject_merge(&mut self, merge_id: Uuid) -> bool {
+        let before = self.merges.len();
+        self.merges.retain(|m| m.id != merge_id);
+        self.merges.len() != before
     }
 
     /// Check whether processing a task would violate the agent's resource limits.
     ///
     /// Returns `Ok(())` if within limits, or `Err(reason)` if a limit would be exceeded.
     pub fn check_resource_limits(&self, agent_id: &Uuid) -> Result<(), String> {
-        let limits = self
-            .spawner
-            .get(agent_id)
-            .map(|ctx| &ctx.resource_limits)
-            .cloned()
-            .unwrap_or_default();
+        let Some(ctx) = self.spawner.get(agent_id) else {
+            return Ok(());
+        };
+        let limits = &ctx.resource_limits;
+
+        if ctx.status == AgentStatus::Terminated {
+            return Err(format!(
+                "Agent {} was terminated after exceeding its resource limits",
+                agent_id
+            ));
+        }
 
         // Check tool call limit
         if let Some(max_calls) = limits.max_tool_calls {
-            let current = self.tool_call_count(agent_id);
+            let current = ctx.usage.tool_calls();
             if current >= max_calls {
                 return Err(format!(
                     "Agent {} exceeded max_tool_calls limit ({}/{})",
@@ -114,34 +333,75 @@ impl AgentOrchestrator {
             }
         }
 
+        // Check wall-clock limit
+        if ctx
+            .usage
+            .remaining_runtime(limits)
+            .is_some_and(|left| left.is_zero())
+        {
+            return Err(format!(
+                "Agent {} exceeded max_runtime_secs limit ({}s)",
+                agent_id,
+                limits.max_runtime_secs.unwrap_or(0)
+            ));
+        }
+
         Ok(())
     }
 
+    /// A fresh scope for the agent's next task.
+    fn task_scope(&self, agent_id: Uuid) -> Option<TaskScope> {
+        let ctx = self.spawner.get(&agent_id)?;
+        Some(TaskScope {
+            agent_id,
+            tools: self.spawner.tools_for(&agent_id)?,
+            limits: ctx.resource_limits.clone(),
+            usage: Arc::clone(&ctx.usage),
+            facts: Vec::new(),
+            partial: String::new(),
+        })
+    }
+
     /// Process all pending messages for all registered agents.
     ///
     /// For each agent with pending messages:
     /// 1. Check resource limits
     /// 2. Receive the message
     /// 3. Dispatch to the registered handler (for TaskRequest)
-    /// 4. Send the result back via the bus
+    /// 4. Send the result back via the bus and acknowledge the message
+    ///
+    /// Agents whose next message has the highest priority are served first,
+    /// so critical messages are handled before bulk work. Messages left
+    /// unacknowledged by an earlier, interrupted pass are redelivered.
     ///
     /// Returns the number of messages processed.
     pub async fn process_pending(&mut self) -> usize {
+        self.bus.sweep();
+
         // Collect agent IDs that have pending messages and handlers
-        let agent_ids: Vec<Uuid> = self
+        let mut agent_ids: Vec<Uuid> = self
             .handlers
             .keys()
             .filter(|id| self.bus.pending_count(id) > 0)
             .copied()
             .collect();
+        agent_ids.sort_by_key(|id| {
+            std::cmp::Reverse(
+                self.bus
+                    .peek(id)
+                    .map(|e| (e.priority, std::cmp::Reverse(e.created_at))),
+            )
+        });
 
         let mut processed = 0;
+        let mut deferred = Vec::new();
 
         for agent_id in agent_ids {
             // Check resource limits before processing
             if let Err(reason) = self.check_resource_limits(&agent_id) {
                 // Send an error back if there's a pending message
                 if let Some(envelope) = self.bus.receive(&agent_id) {
+                    self.bus.ack(&envelope.id);
                     let error_response = AgentEnvelope::new(
                         agent_id,
                         envelope.from,
@@ -171,40 +431,33 @@ impl AgentOrchestrator {
 
             match &envelope.payload {
                 AgentPayload::TaskRequest { description, args } => {
-                    // Increment tool call count
-                    *self.tool_call_counts.entry(agent_id).or_insert(0) += 1;
-
-                    let handler = match self.handlers.get(&agent_id) {
-                        Some(h) => h,
-                        None => continue,
+                    let (Some(handler), Some(mut scope)) =
+                        (self.handlers.get(&agent_id), self.task_scope(agent_id))
+                    else {
+                        continue;
                     };
 
-                    let result = handler.handle_task(description, args).await;
+                    let result = run_scoped(handler.as_ref(), description, args, &mut scope).await;
 
-                    let response_payload = match result {
-                        Ok(output) => AgentPayload::TaskResult {
-                            success: true,
-                            output,
-                        },
-                        Err(err) => AgentPayload::TaskResult {
-                            success: false,
-                            output: err,
-                        },
-                    };
-
-                    let mut response =
-                        AgentEnvelope::new(agent_id, envelope.from, response_payload);
-                    if let Some(corr) = envelope.correlation_id {
-                        response = response.with_correlation(corr);
+                    // The provider queue was full: retry after the rest of this
+                    // pass instead of failing the task.
+                    if let Err(err) = &result
+                        && LlmError::is_saturation_message(err)
+                    {
+                        deferred.push((agent_id, envelope));
+                        continue;
                     }
-                    let _ = self.bus.send(response);
+
+                    let result = self.finish_task(agent_id, scope, result);
+                    self.send_task_result(agent_id, &envelope, result);
+                    self.bus.ack(&envelope.id);
                     processed += 1;
                 }
                 AgentPayload::Shutdown => {
+                    self.bus.ack(&envelope.id);
                     // Terminate the agent and its children
                     self.spawner.terminate(agent_id);
                     self.handlers.remove(&agent_id);
-                    self.tool_call_counts.remove(&agent_id);
                     processed += 1;
                 }
                 AgentPayload::StatusQuery => {
@@ -224,23 +477,174 @@ impl AgentOrchestrator {
                         },
                     );
                     let _ = self.bus.send(response);
+                    self.bus.ack(&envelope.id);
                     processed += 1;
                 }
                 _ => {
                     // Other payload types are forwarded as-is (no special handling)
+                    self.bus.ack(&envelope.id);
                     processed += 1;
                 }
             }
         }
 
+        // Tasks deferred because their provider was saturated run one at a
+        // time, now that the rest of the pass has released its request slots.
+        if !deferred.is_empty() {
+            tracing::info!(
+                tasks = deferred.len(),
+                "Provider saturated; running deferred tasks sequentially"
+            );
+        }
+        for (agent_id, envelope) in deferred {
+            let AgentPayload::TaskRequest { description, args } = &envelope.payload else {
+                continue;
+            };
+            let (Some(handler), Some(mut scope)) =
+                (self.handlers.get(&agent_id), self.task_scope(agent_id))
+            else {
+                continue;
+            };
+            let result = run_scoped(handler.as_ref(), description, args, &mut scope).await;
+            let result = self.finish_task(agent_id, scope, result);
+            self.send_task_result(agent_id, &envelope, result);
+            self.bus.ack(&envelope.id);
+            processed += 1;
+        }
+
         processed
     }
+
+    /// Settle a finished task: audit its violations under the agent's ID,
+    /// keep the facts it learned in the agent's memory (queueing a merge for
+    /// a child), and terminate the agent if it ran out of a resource,
+    /// returning its partial result.
+    fn finish_task(
+        &mut self,
+        agent_id: Uuid,
+        scope: TaskScope,
+        result: Result<String, String>,
+    ) -> Result<String, String> {
+        let violations = scope.usage.take_violations();
+        let Some(ctx) = self.spawner.get_mut(&agent_id) else {
+            return result;
+        };
+        for violation in &violations {
+            tracing::warn!(
+                agent = %agent_id,
+                limit = violation.kind.as_str(),
+                "{}",
+                violation.detail
+            );
+            ctx.safety.log_agent_violation(
+                agent_id,
+                violation.tool.as_deref().unwrap_or(""),
+                violation.kind.as_str(),
+                &violation.detail,
+            );
+        }
+
+        for fact in &scope.facts {
+            ctx.memory.add_fact(fact.clone());
+        }
+        if let Some(parent_id) = ctx.parent_id
+            && !scope.facts.is_empty()
+        {
+            self.merges.push(MemoryMerge {
+                id: Uuid::new_v4(),
+                agent_id,
+                parent_id,
+                facts: scope.facts,
+                created_at: Utc::now(),
+            });
+        }
+
+        let Some(violation) = violations.iter().find(|v| v.kind.terminates()) else {
+            return result;
+        };
+        ctx.status = AgentStatus::Terminated;
+        let output = match result {
+            Ok(output) if !output.is_empty() => output,
+            _ if !scope.partial.is_empty() => scope.partial,
+            Ok(output) | Err(output) => output,
+        };
+        Err(format!(
+            "Agent '{}' terminated: {}. Partial result:\n{}",
+            ctx.name, violation.detail, output
+        ))
+    }
+
+    /// Send a `TaskResult` for a completed task request back to its sender.
+    fn send_task_result(
+        &mut self,
+        agent_id: Uuid,
+        envelope: &AgentEnvelope,
+        result: Result<String, String>,
+    ) {
+        let response_payload = match result {
+            Ok(output) => AgentPayload::TaskResult {
+                success: true,
+                output,
+            },
+            Err(err) => AgentPayload::TaskResult {
+                success: false,
+                output: err,
+            },
+        };
+
+        let mut response = AgentEnvelope::new(agent_id, envelope.from, response_payload);
+        if let Some(corr) = envelope.correlation_id {
+            response = response.with_correlation(corr);
+        }
+        let _ = self.bus.send(response);
+    }
+}
+
+/// Run one task under the agent's remaining runtime. A task cut off by
+/// `max_runtime_secs` returns its partial output as the error.
+async fn run_scoped(
+    handler: &dyn TaskHandler,
+    description: &str,
+    args: &HashMap<String, String>,
+    scope: &mut TaskScope,
+) -> Result<String, String> {
+    let usage = Arc::clone(&scope.usage);
+    let remaining = usage.remaining_runtime(&scope.limits);
+    usage.begin_task();
+    let result = match remaining {
+        Some(remaining) => {
+            match tokio::time::timeout(
+                remaining,
+                handler.handle_scoped_task(description, args, scope),
+            )
+            .await
+            {
+                Ok(result) => result,
+                Err(_) => {
+                    usage.record(LimitViolation {
+                        kind: LimitKind::Runtime,
+                        tool: None,
+                        detail: format!(
+                            "max_runtime_secs reached ({}s)",
+                            scope.limits.max_runtime_secs.unwrap_or(0)
+                        ),
+                    });
+                    Err(std::mem::take(&mut scope.partial))
+                }
+            }
+        }
+        None => handler.handle_scoped_task(description, args, scope).await,
+    };
+    usage.end_task();
+    result
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::multi::spawner::SpawnerConfig;
+    use crate::agent::RegisteredTool;
+    use crate::multi::spawner::{SpawnRequest, SpawnerConfig};
+    use crate::types::{RiskLevel, ToolDefinition, ToolOutput};
 
     struct EchoHandler;
 
@@ -268,6 +672,36 @@ mod tests {
         }
     }
 
+    /// Fails with a provider saturation error on the first call only.
+    struct SaturatedOnceHandler(std::sync::atomic::AtomicUsize);
+
+    #[async_trait]
+    impl TaskHandler for SaturatedOnceHandler {
+        async fn handle_task(
+            &self,
+            description: &str,
+            _args: &HashMap<String, String>,
+        ) -> Result<String, String> {
+            if self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                return Err(LlmError::Saturated {
+                    provider: "openai|default|OPENAI_API_KEY".into(),
+                    waited_ms: 120_000,
+                }
+                .to_string());
+            }
+            Ok(format!("done: {}", description))
+        }
+    }
+
+    fn simulate_tool_calls(orch: &AgentOrchestrator, agent_id: Uuid, calls: u32) {
+        let ctx = orch.spawner().get(&agent_id).unwrap();
+        for _ in 0..calls {
+            ctx.usage
+                .charge_tool_call(&ResourceLimits::default(), "echo")
+                .unwrap();
+        }
+    }
+
     fn setup_orchestrator() -> (AgentOrchestrator, Uuid) {
         let mut spawner = AgentSpawner::default();
         let agent_id = spawner.spawn("test-agent").unwrap();
@@ -507,21 +941,124 @@ mod tests {
 
     #[test]
     fn test_tool_call_count_tracking() {
-        let spawner = AgentSpawner::default();
+        let mut spawner = AgentSpawner::default();
+        let agent_id = spawner.spawn("counted").unwrap();
         let bus = MessageBus::new(100);
         let router = AgentRouter::new();
         let mut orch = AgentOrchestrator::new(spawner, bus, router);
 
-        let agent_id = Uuid::new_v4();
         assert_eq!(orch.tool_call_count(&agent_id), 0);
+        assert_eq!(orch.tool_call_count(&Uuid::new_v4()), 0);
 
-        orch.tool_call_counts.insert(agent_id, 5);
+        simulate_tool_calls(&orch, agent_id, 5);
         assert_eq!(orch.tool_call_count(&agent_id), 5);
 
         orch.reset_tool_counts(&agent_id);
         assert_eq!(orch.tool_call_count(&agent_id), 0);
     }
 
+    #[tokio::test]
+    async fn test_orchestrator_defers_saturated_tasks() {
+        let mut spawner = AgentSpawner::default();
+        let agent_id = spawner.spawn("busy-agent").unwrap();
+        let sender_id = spawner.spawn("sender").unwrap();
+
+        let mut bus = MessageBus::new(100);
+        bus.register(agent_id);
+        bus.register(sender_id);
+
+        let mut orch = AgentOrchestrator::new(spawner, bus, AgentRouter::new());
+        orch.register_handler(
+            agent_id,
+            Box::new(SaturatedOnceHandler(std::sync::atomic::AtomicUsize::new(0))),
+        );
+
+        let task = AgentEnvelope::new(
+            sender_id,
+            agent_id,
+            AgentPayload::TaskRequest {
+                description: "summarize".into(),
+                args: HashMap::new(),
+            },
+        );
+        orch.bus_mut().send(task).unwrap();
+
+        assert_eq!(orch.process_pending().await, 1);
+        let response = orch.bus_mut().receive(&sender_id).unwrap();
+        match &response.payload {
+            AgentPayload::TaskResult { success, output } => {
+                assert!(success);
+                assert_eq!(output, "done: summarize");
+            }
+            _ => panic!("Expected TaskResult"),
+        }
+    }
+
+    /// Records the order tasks are handled in.
+    struct RecordingHandler(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl TaskHandler for RecordingHandler {
+        async fn handle_task(
+            &self,
+            description: &str,
+            _args: &HashMap<String, String>,
+        ) -> Result<String, String> {
+            self.0.lock().unwrap().push(description.to_string());
+            Ok(String::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_orchestrator_serves_critical_first_and_acks() {
+        let mut spawner = AgentSpawner::default();
+        let bulk_agent = spawner.spawn("bulk").unwrap();
+        let urgent_agent = spawner.spawn("urgent").unwrap();
+        let sender_id = spawner.spawn("sender").unwrap();
+
+        let mut bus = MessageBus::new(100);
+        for id in [bulk_agent, urgent_agent, sender_id] {
+            bus.register(id);
+        }
+        let mut orch = AgentOrchestrator::new(spawner, bus, AgentRouter::new());
+        let order = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        for id in [bulk_agent, urgent_agent] {
+            orch.register_handler(id, Box::new(RecordingHandler(order.clone())));
+        }
+
+        let task = |to: Uuid, description: &str| {
+            AgentEnvelope::new(
+                sender_id,
+                to,
+                AgentPayload::TaskRequest {
+                    description: description.into(),
+                    args: HashMap::new(),
+                },
+            )
+        };
+        orch.bus_mut()
+            .send(task(bulk_agent, "bulk").with_priority(MessagePriority::Low))
+            .unwrap();
+        orch.bus_mut()
+            .send(task(urgent_agent, "urgent").with_priority(MessagePriority::Critical))
+            .unwrap();
+
+        let status = orch.status();
+        assert_eq!(status.queued, 2);
+        assert_eq!(status.queue_depths[&urgent_agent], 1);
+
+        assert_eq!(orch.process_pending().await, 2);
+        assert_eq!(*order.lock().unwrap(), vec!["urgent", "bulk"]);
+
+        let status = orch.status();
+        assert_eq!(status.agents, 3);
+        assert_eq!(status.handlers, 2);
+        assert_eq!(status.queued, 2, "two results for the sender");
+        assert_eq!(status.in_flight, 0, "handled tasks are acknowledged");
+        assert_eq!(status.dead_letters, 0);
+        assert!(orch.dead_letters().is_empty());
+    }
+
     #[tokio::test]
     async fn test_orchestrator_no_pending_returns_zero() {
         let (mut orch, _) = setup_orchestrator();
@@ -641,13 +1178,242 @@ mod tests {
 
         let bus = MessageBus::new(100);
         let router = AgentRouter::new();
-        let mut orch = AgentOrchestrator::new(spawner, bus, router);
+        let orch = AgentOrchestrator::new(spawner, bus, router);
 
-        // Simulate 3 tool calls
-        orch.tool_call_counts.insert(agent_id, 3);
+        simulate_tool_calls(&orch, agent_id, 3);
 
         let result = orch.check_resource_limits(&agent_id);
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("max_tool_calls"));
     }
+
+    /// Tools where `file_write` flips `wrote` instead of touching disk.
+    fn test_tools(
+        wrote: Arc<std::sync::atomic::AtomicBool>,
+    ) -> HashMap<String, Arc<RegisteredTool>> {
+        let tool = |name: &str, executor: crate::agent::ToolExecutor| {
+            (
+                name.to_string(),
+                Arc::new(RegisteredTool {
+                    definition: ToolDefinition {
+                        name: name.into(),
+                        description: String::new(),
+                        parameters: serde_json::json!({"type": "object"}),
+                    },
+                    risk_level: RiskLevel::Write,
+                    executor,
+                }),
+            )
+        };
+        HashMap::from([
+            tool(
+                "file_write",
+                Box::new(move |_| {
+                    let wrote = wrote.clone();
+                    Box::pin(async move {
+                        wrote.store(true, std::sync::atomic::Ordering::SeqCst);
+                        Ok(ToolOutput::text("written"))
+                    })
+                }),
+            ),
+            tool(
+                "web_fetch",
+                Box::new(|_| Box::pin(async { Ok(ToolOutput::text("page")) })),
+            ),
+        ])
+    }
+
+    /// Acts like a model that asks for `file_write`, then for `web_fetch`
+    /// until refused, remembering what it read.
+    struct ResearchHandler;
+
+    #[async_trait]
+    impl TaskHandler for ResearchHandler {
+        async fn handle_task(
+            &self,
+            _description: &str,
+            _args: &HashMap<String, String>,
+        ) -> Result<String, String> {
+            unreachable!("scoped handler")
+        }
+
+        async fn handle_scoped_task(
+            &self,
+            description: &str,
+            _args: &HashMap<String, String>,
+            scope: &mut TaskScope,
+        ) -> Result<String, String> {
+            assert_eq!(scope.tools.names(), vec!["web_fetch".to_string()]);
+            let write = scope
+                .tools
+                .execute("file_write", serde_json::json!({"path": "notes.md"}))
+                .await;
+            let mut notes = vec![format!("write: {}", write.unwrap_err())];
+            for page in 0..3 {
+                match scope
+                    .tools
+                    .execute("web_fetch", serde_json::json!({}))
+                    .await
+                {
+                    Ok(_) => {
+                        scope.progress(&format!("read page {}", page));
+                        scope.remember(Fact::new(format!("{} page {}", description, page), "web"));
+                    }
+                    Err(e) => return Err(e.to_string()),
+                }
+            }
+            notes.push("read all pages".into());
+            Ok(notes.join("\n"))
+        }
+    }
+
+    fn research_orchestrator(
+        limits: ResourceLimits,
+    ) -> (
+        AgentOrchestrator,
+        Uuid,
+        Uuid,
+        Arc<std::sync::atomic::AtomicBool>,
+    ) {
+        let wrote = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let mut config = SpawnerConfig::default();
+        config.roles.insert(
+            "research".into(),
+            crate::config::AgentRoleConfig {
+                tools: vec!["web_fetch".into(), "web_search".into()],
+                resource_limits: Some(limits),
+            },
+        );
+        let mut spawner = AgentSpawner::new(config).with_tools(test_tools(wrote.clone()));
+        let parent = spawner.spawn("lead").unwrap();
+        let child = spawner
+            .spawn_request(
+                SpawnRequest::new("researcher")
+                    .with_parent(parent)
+                    .with_role("research"),
+            )
+            .unwrap();
+        let mut bus = MessageBus::new(100);
+        bus.register(parent);
+        bus.register(child);
+        let mut orch = AgentOrchestrator::new(spawner, bus, AgentRouter::new());
+        orch.register_handler(child, Box::new(ResearchHandler));
+        (orch, parent, child, wrote)
+    }
+
+    fn send_task(orch: &mut AgentOrchestrator, from: Uuid, to: Uuid, description: &str) {
+        let task = AgentEnvelope::new(
+            from,
+            to,
+            AgentPayload::TaskRequest {
+                description: description.into(),
+                args: HashMap::new(),
+            },
+        );
+        orch.bus_mut().send(task).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_child_without_file_tools_cannot_write() {
+        let (mut orch, parent, child, wrote) = research_orchestrator(ResourceLimits::default());
+        send_task(&mut orch, parent, child, "rust async");
+        orch.process_pending().await;
+
+        assert!(!wrote.load(std::sync::atomic::Ordering::SeqCst));
+        let response = orch.bus_mut().receive(&parent).unwrap();
+        match &response.payload {
+            AgentPayload::TaskResult { success, output } => {
+                assert!(success, "a refused tool does not terminate the agent");
+                assert!(
+                    output.contains("not in this agent's tool allowlist"),
+                    "{}",
+                    output
+                );
+            }
+            _ => panic!("Expected TaskResult"),
+        }
+
+        let ctx = orch.spawner().get(&child).unwrap();
+        assert!(ctx.safety.audit_log().iter().any(|entry| matches!(
+            &entry.event,
+            crate::safety::AuditEvent::AgentViolation { agent_id, tool, limit, .. }
+                if *agent_id == child && tool == "file_write" && limit == "tool_not_permitted"
+        )));
+        let usage = orch.agent_usage(&child).unwrap();
+        assert_eq!(
+            usage.usage.tool_calls, 3,
+            "only permitted calls are counted"
+        );
+        assert_eq!(usage.usage.violations, 1);
+        assert_eq!(usage.status, AgentStatus::Idle);
+    }
+
+    #[tokio::test]
+    async fn test_limit_terminates_agent_and_merge_is_explicit() {
+        let limits = ResourceLimits {
+            max_tool_calls: Some(2),
+            ..Default::default()
+        };
+        let (mut orch, parent, child, _) = research_orchestrator(limits);
+        send_task(&mut orch, parent, child, "rust async");
+        orch.process_pending().await;
+
+        let response = orch.bus_mut().receive(&parent).unwrap();
+        match &response.payload {
+            AgentPayload::TaskResult { success, output } => {
+                assert!(!success);
+                assert!(output.contains("terminated: max_tool_calls limit reached (2/2)"));
+                assert!(output.ends_with("read page 0\nread page 1"), "{}", output);
+            }
+            _ => panic!("Expected TaskResult"),
+        }
+        let status = orch.status();
+        let usage = &status.usage[&child];
+        assert_eq!(usage.status, AgentStatus::Terminated);
+        assert!(
+            usage.summary().starts_with("researcher: 2/2 tool calls"),
+            "{}",
+            usage.summary()
+        );
+
+        // Further tasks are refused without running.
+        send_task(&mut orch, parent, child, "more");
+        orch.process_pending().await;
+        let refused = orch.bus_mut().receive(&parent).unwrap();
+        assert!(
+            matches!(refused.payload, AgentPayload::Error { ref code, .. } if code == "RESOURCE_LIMIT")
+        );
+
+        // Facts stay with the child until the parent accepts the merge.
+        assert_eq!(
+            orch.spawner()
+                .get(&child)
+                .unwrap()
+                .memory
+                .long_term
+                .facts
+                .len(),
+            2
+        );
+        assert!(
+            orch.spawner()
+                .get(&parent)
+                .unwrap()
+                .memory
+                .long_term
+                .facts
+                .is_empty()
+        );
+        assert_eq!(status.pending_merges, 1);
+        let merge = orch.pending_merges()[0].clone();
+        assert_eq!(merge.agent_id, child);
+        let merged = orch
+            .apply_merge(merge.id, Some(&[merge.facts[1].id]))
+            .unwrap();
+        assert_eq!(merged, 1);
+        let facts = &orch.spawner().get(&parent).unwrap().memory.long_term.facts;
+        assert_eq!(facts.len(), 1);
+        assert_eq!(facts[0].content, "rust async page 1");
+        assert!(orch.pending_merges().is_empty());
+    }
 }
@@ -1,12 +1,16 @@
 //! Agent spawner — lifecycle management for agents.
 //!
 //! Manages creating and terminating agents, enforces limits, and tracks
-//! parent-child relationships for hierarchical agent spawning.
-
-use super::isolation::{AgentContext, AgentStatus, ResourceLimits};
-use crate::config::SafetyConfig;
-use std::collections::HashMap;
+//! parent-child relationships for hierarchical agent spawning. Agents spawned
+//! from a [`SpawnRequest`] get a tool allowlist from their role, and a child
+//! never gets a tool its parent lacks.
+
+use super::isolation::{AgentContext, AgentStatus, ResourceLimits, ScopedTools};
+use crate::agent::RegisteredTool;
+use crate::config::{AgentRoleConfig, MultiAgentConfig, SafetyConfig};
+use std::collections::{BTreeSet, HashMap};
 use std::path::PathBuf;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Configuration for multi-agent spawning.
@@ -18,6 +22,10 @@ pub struct SpawnerConfig {
     pub default_window_size: usize,
     /// Default safety config applied to spawned agents.
     pub default_safety: SafetyConfig,
+    /// Default resource limits applied to spawned agents.
+    pub default_resource_limits: ResourceLimits,
+    /// Named roles with their tool allowlists.
+    pub roles: HashMap<String, AgentRoleConfig>,
 }
 
 impl Default for SpawnerConfig {
@@ -26,14 +34,76 @@ impl Default for SpawnerConfig {
             max_agents: 8,
             default_window_size: 10,
             default_safety: SafetyConfig::default(),
+            default_resource_limits: ResourceLimits::default(),
+            roles: HashMap::new(),
         }
     }
 }
 
+impl SpawnerConfig {
+    /// The limits and roles described by the `[multi_agent]` config section.
+    pub fn from_config(config: &MultiAgentConfig) -> Self {
+        Self {
+            max_agents: config.max_agents,
+            default_resource_limits: config.default_resource_limits.clone(),
+            roles: config.roles.clone(),
+            ..Self::default()
+        }
+    }
+}
+
+/// What to spawn: a name plus optional parent, role, tool allowlist and limits.
+#[derive(Debug, Clone, Default)]
+pub struct SpawnRequest {
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    /// A role from [`SpawnerConfig::roles`].
+    pub role: Option<String>,
+    /// Tools the agent may call, narrowed further by its role and parent.
+    pub tools: Option<Vec<String>>,
+    pub resource_limits: Option<ResourceLimits>,
+    pub workspace_dir: Option<PathBuf>,
+    pub llm_override: Option<String>,
+}
+
+impl SpawnRequest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_parent(mut self, parent_id: Uuid) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+
+    pub fn with_role(mut self, role: impl Into<String>) -> Self {
+        self.role = Some(role.into());
+        self
+    }
+
+    pub fn with_tools<I, S>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tools = Some(tools.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.resource_limits = Some(limits);
+        self
+    }
+}
+
 /// Manages agent lifecycle — spawn, terminate, query.
 pub struct AgentSpawner {
     config: SpawnerConfig,
     contexts: HashMap<Uuid, AgentContext>,
+    tools: HashMap<String, Arc<RegisteredTool>>,
 }
 
 impl AgentSpawner {
@@ -41,26 +111,19 @@ impl AgentSpawner {
         Self {
             config,
             contexts: HashMap::new(),
+            tools: HashMap::new(),
         }
     }
 
+    /// Set the full tool registry that agents' scoped tools are drawn from.
+    pub fn with_tools(mut self, tools: HashMap<String, Arc<RegisteredTool>>) -> Self {
+        self.tools = tools;
+        self
+    }
+
     /// Spawn a new top-level agent. Returns the agent ID, or an error if limit reached.
     pub fn spawn(&mut self, name: impl Into<String>) -> Result<Uuid, String> {
-        if self.contexts.len() >= self.config.max_agents {
-            return Err(format!(
-                "Agent limit reached (max {})",
-                self.config.max_agents
-            ));
-        }
-
-        let ctx = AgentContext::new(
-            name,
-            self.config.default_window_size,
-            self.config.default_safety.clone(),
-        );
-        let id = ctx.agent_id;
-        self.contexts.insert(id, ctx);
-        Ok(id)
+        self.spawn_request(SpawnRequest::new(name))
     }
 
     /// Spawn a child agent under a parent. Returns the child's ID.
@@ -69,27 +132,85 @@ impl AgentSpawner {
         name: impl Into<String>,
         parent_id: Uuid,
     ) -> Result<Uuid, String> {
-        if !self.contexts.contains_key(&parent_id) {
-            return Err(format!("Parent agent {} not found", parent_id));
-        }
+        self.spawn_request(SpawnRequest::new(name).with_parent(parent_id))
+    }
+
+    /// Spawn an agent from a request. Its tool allowlist is the
+    /// intersection of the request's tools, its role's tools and its
+    /// parent's allowlist; limits come from the request, then the role, then
+    /// the spawner defaults.
+    pub fn spawn_request(&mut self, request: SpawnRequest) -> Result<Uuid, String> {
+        let parent = match request.parent_id {
+            Some(parent_id) => Some(
+                self.contexts
+                    .get(&parent_id)
+                    .ok_or_else(|| format!("Parent agent {} not found", parent_id))?,
+            ),
+            None => None,
+        };
         if self.contexts.len() >= self.config.max_agents {
             return Err(format!(
                 "Agent limit reached (max {})",
                 self.config.max_agents
             ));
         }
+        let role = match &request.role {
+            Some(name) => Some(
+                self.config
+                    .roles
+                    .get(name)
+                    .ok_or_else(|| format!("Unknown agent role '{}'", name))?,
+            ),
+            None => None,
+        };
 
-        let ctx = AgentContext::new_child(
-            name,
-            parent_id,
-            self.config.default_window_size,
-            self.config.default_safety.clone(),
-        );
+        let mut allowlist: Option<BTreeSet<String>> = None;
+        let sources = [
+            request.tools.as_ref().map(|t| t.iter().cloned().collect()),
+            role.map(|r| r.tools.iter().cloned().collect()),
+            parent.and_then(|p| p.tool_allowlist.clone()),
+        ];
+        for tools in sources.into_iter().flatten() {
+            allowlist = Some(match allowlist {
+                Some(current) => current.intersection(&tools).cloned().collect(),
+                None => tools,
+            });
+        }
+        let limits = request
+            .resource_limits
+            .or_else(|| role.and_then(|r| r.resource_limits.clone()))
+            .unwrap_or_else(|| self.config.default_resource_limits.clone());
+
+        let mut ctx = match request.parent_id {
+            Some(parent_id) => AgentContext::new_child(
+                request.name,
+                parent_id,
+                self.config.default_window_size,
+                self.config.default_safety.clone(),
+            ),
+            None => AgentContext::new(
+                request.name,
+                self.config.default_window_size,
+                self.config.default_safety.clone(),
+            ),
+        };
+        ctx.role = request.role;
+        ctx.tool_allowlist = allowlist;
+        ctx.resource_limits = limits;
+        ctx.workspace_dir = request.workspace_dir;
+        ctx.llm_override = request.llm_override;
         let id = ctx.agent_id;
         self.contexts.insert(id, ctx);
         Ok(id)
     }
 
+    /// The tools an agent may call, drawn from the spawner's registry.
+    pub fn tools_for(&self, agent_id: &Uuid) -> Option<ScopedTools> {
+        self.contexts
+            .get(agent_id)
+            .map(|ctx| ctx.scoped_tools(&self.tools))
+    }
+
     /// Terminate an agent and all its children. Returns number of agents removed.
     pub fn terminate(&mut self, agent_id: Uuid) -> usize {
         let children = self.children_of(agent_id);
@@ -143,24 +264,12 @@ impl AgentSpawner {
         llm_override: Option<String>,
         resource_limits: ResourceLimits,
     ) -> Result<Uuid, String> {
-        if self.contexts.len() >= self.config.max_agents {
-            return Err(format!(
-                "Agent limit reached (max {})",
-                self.config.max_agents
-            ));
-        }
-
-        let mut ctx = AgentContext::new(
-            name,
-            self.config.default_window_size,
-            self.config.default_safety.clone(),
-        );
-        ctx.workspace_dir = workspace_dir;
-        ctx.llm_override = llm_override;
-        ctx.resource_limits = resource_limits;
-        let id = ctx.agent_id;
-        self.contexts.insert(id, ctx);
-        Ok(id)
+        self.spawn_request(SpawnRequest {
+            workspace_dir,
+            llm_override,
+            resource_limits: Some(resource_limits),
+            ..SpawnRequest::new(name)
+        })
     }
 
     /// Get the status of an agent.
@@ -312,6 +421,45 @@ mod tests {
         assert_eq!(ctx.resource_limits.max_memory_mb, Some(256));
     }
 
+    #[test]
+    fn test_spawn_request_narrows_tools_by_role_and_parent() {
+        let mut config = SpawnerConfig::default();
+        config.roles.insert(
+            "research".into(),
+            AgentRoleConfig {
+                tools: vec!["web_fetch".into(), "web_search".into(), "file_read".into()],
+                resource_limits: Some(ResourceLimits {
+                    max_tool_calls: Some(5),
+                    ..Default::default()
+                }),
+            },
+        );
+        let mut spawner = AgentSpawner::new(config);
+        let parent = spawner
+            .spawn_request(SpawnRequest::new("lead").with_role("research"))
+            .unwrap();
+        let child = spawner
+            .spawn_request(
+                SpawnRequest::new("reader")
+                    .with_parent(parent)
+                    .with_tools(["file_read", "file_write"]),
+            )
+            .unwrap();
+
+        let ctx = spawner.get(&child).unwrap();
+        assert!(ctx.permits_tool("file_read"));
+        assert!(!ctx.permits_tool("file_write"), "parent lacks file_write");
+        assert!(!ctx.permits_tool("web_fetch"), "not requested");
+        assert_eq!(
+            spawner.get(&parent).unwrap().resource_limits.max_tool_calls,
+            Some(5)
+        );
+        assert!(ctx.resource_limits.max_tool_calls.is_none());
+
+        let unknown = spawner.spawn_request(SpawnRequest::new("x").with_role("admin"));
+        assert!(unknown.unwrap_err().contains("Unknown agent role"));
+    }
+
     #[test]
     fn test_get_set_status() {
         let mut spawner = AgentSpawner::default();
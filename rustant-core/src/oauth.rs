@@ -827,6 +827,36 @@ pub async fn refresh_token(
     Ok(token)
 }
 
+/// Load the stored token for `provider`, refreshing and re-storing it if it
+/// has expired, and return a usable access token.
+pub async fn resolve_access_token(
+    store: &dyn CredentialStore,
+    provider: &str,
+) -> Result<String, LlmError> {
+    let token = load_oauth_token(store, provider)?;
+    if !is_token_expired(&token) {
+        return Ok(token.access_token);
+    }
+    let Some(ref rt) = token.refresh_token else {
+        return Err(LlmError::OAuthFailed {
+            message: format!(
+                "OAuth token for '{}' has expired and no refresh token is available. \
+                 Please re-authenticate with: rustant auth login {}",
+                provider, provider
+            ),
+        });
+    };
+    let oauth_cfg = oauth_config_for_provider(provider).ok_or_else(|| LlmError::OAuthFailed {
+        message: format!(
+            "OAuth not supported for provider '{}' — cannot refresh token",
+            provider
+        ),
+    })?;
+    let new_token = refresh_token(&oauth_cfg, rt).await?;
+    store_oauth_token(store, provider, &new_token)?;
+    Ok(new_token.access_token)
+}
+
 // ── Token Expiration ────────────────────────────────────────────────────────
 
 /// Check whether an OAuth token has expired (with a 5-minute safety buffer).
@@ -1082,6 +1112,30 @@ pub fn gmail_oauth_config(client_id: &str, client_secret: Option<String>) -> OAu
     }
 }
 
+/// OAuth configuration for Outlook / Microsoft 365 mail (IMAP/SMTP with XOAUTH2).
+///
+/// Uses the Microsoft identity platform's "common" endpoint with the Outlook
+/// IMAP and SMTP scopes. Requires an Azure AD app registration client ID.
+pub fn outlook_oauth_config(client_id: &str, client_secret: Option<String>) -> OAuthProviderConfig {
+    let base = "https://login.microsoftonline.com/common/oauth2/v2.0";
+    OAuthProviderConfig {
+        provider_name: "outlook".to_string(),
+        client_id: client_id.to_string(),
+        client_secret,
+        authorization_url: format!("{}/authorize", base),
+        token_url: format!("{}/token", base),
+        scopes: vec![
+            "https://outlook.office.com/IMAP.AccessAsUser.All".to_string(),
+            "https://outlook.office.com/SMTP.Send".to_string(),
+            "offline_access".to_string(),
+        ],
+        audience: None,
+        supports_device_code: true,
+        device_code_url: Some(format!("{}/devicecode", base)),
+        extra_auth_params: vec![],
+    }
+}
+
 // ── Client Credentials Flow ────────────────────────────────────────────────
 
 /// Run the OAuth 2.0 Client Credentials flow (server-to-server).
@@ -1199,6 +1253,11 @@ pub fn oauth_config_for_provider(provider: &str) -> Option<OAuthProviderConfig>
                 .ok();
             Some(gmail_oauth_config(&client_id, client_secret))
         }
+        "outlook" => {
+            let client_id = std::env::var("OUTLOOK_OAUTH_CLIENT_ID").ok()?;
+            let client_secret = std::env::var("OUTLOOK_OAUTH_CLIENT_SECRET").ok();
+            Some(outlook_oauth_config(&client_id, client_secret))
+        }
         _ => None,
     }
 }
@@ -1219,6 +1278,7 @@ pub fn oauth_config_with_credentials(
         "slack" => Some(slack_oauth_config(client_id, secret)),
         "discord" => Some(discord_oauth_config(client_id, secret)),
         "gmail" => Some(gmail_oauth_config(client_id, secret)),
+        "outlook" => Some(outlook_oauth_config(client_id, secret)),
         _ => None,
     }
 }
@@ -1236,6 +1296,7 @@ pub fn provider_supports_oauth(provider: &str) -> bool {
             std::env::var("GMAIL_OAUTH_CLIENT_ID").is_ok()
                 || std::env::var("GOOGLE_OAUTH_CLIENT_ID").is_ok()
         }
+        "outlook" => std::env::var("OUTLOOK_OAUTH_CLIENT_ID").is_ok(),
         _ => false,
     }
 }
@@ -9,10 +9,14 @@
 //! 3. Device computes an HMAC response over the nonce using the shared secret.
 //! 4. Agent verifies the response via [`PairingManager::verify_response`].
 //! 5. On success the device is added to the paired-devices list.
+//!
+//! The gateway pairs phones and browsers by QR code with the same challenge
+//! types; see [`crate::gateway::devices`].
 
 use chrono::{DateTime, Duration, Utc};
 use hmac::{Hmac, Mac};
 use rand::Rng;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use uuid::Uuid;
@@ -24,7 +28,7 @@ type HmacSha256 = Hmac<Sha256>;
 // ---------------------------------------------------------------------------
 
 /// Identity of a paired device.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DeviceIdentity {
     pub device_id: Uuid,
     pub device_name: String,
@@ -34,7 +38,7 @@ pub struct DeviceIdentity {
 }
 
 /// A challenge issued to a device during the pairing flow.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PairingChallenge {
     pub challenge_id: Uuid,
     pub nonce: String,
@@ -237,7 +241,7 @@ impl PairingManager {
 }
 
 /// Compute HMAC-SHA256 and return the hex-encoded result.
-fn compute_hmac(key: &[u8], data: &[u8]) -> String {
+pub(crate) fn compute_hmac(key: &[u8], data: &[u8]) -> String {
     let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take key of any size");
     mac.update(data);
     let result = mac.finalize().into_bytes();
@@ -245,7 +249,7 @@ fn compute_hmac(key: &[u8], data: &[u8]) -> String {
 }
 
 // Tiny hex encoding helper (no external dep needed).
-mod hex {
+pub(crate) mod hex {
     pub fn encode(bytes: impl AsRef<[u8]>) -> String {
         bytes
             .as_ref()
@@ -0,0 +1,1058 @@
+//! Unified person graph: one entity per person across contacts and channels.
+//!
+//! Contacts cards, relationships-tool entries, Slack users, email senders and
+//! iMessage handles are resolved to a single [`Person`] by handle, email
+//! address, phone number and — when unambiguous — name. Auto-matching can be
+//! corrected with [`PersonGraph::merge`] and [`PersonGraph::split`]. Each
+//! person keeps an interaction timeline fed from channel history, from which
+//! [`PersonGraph::suggestions`] derives relationship-maintenance reminders
+//! ("you haven't replied to …") for the daily briefing and the relationships
+//! tool.
+//!
+//! The graph lives in `.rustant/people/graph.json` in the workspace and never
+//! leaves it; [`PersonGraph::forget`] and [`PersonGraph::export`] act on one
+//! person at a time.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::channels::{ChannelMessage, ChannelType, ClassifiedMessage, MessageType};
+
+/// Interactions kept per person; older ones are dropped first.
+const MAX_INTERACTIONS: usize = 200;
+
+/// Sources whose handle IDs are names rather than opaque identifiers.
+const NAMED_SOURCES: &[&str] = &["contacts", "relationships"];
+
+/// Channels whose handle IDs may be phone numbers. Elsewhere long numeric
+/// IDs (Telegram, Discord) are opaque.
+const PHONE_SOURCES: &[&str] = &["contacts", "sms", "imessage", "whatsapp", "signal"];
+
+/// Characters of message text kept as an interaction summary.
+const SUMMARY_CHARS: usize = 120;
+
+/// Errors from person graph operations.
+#[derive(Debug, thiserror::Error)]
+pub enum PeopleError {
+    #[error("No person with id #{id}")]
+    NotFound { id: usize },
+    #[error("Cannot merge person #{id} into itself")]
+    SelfMerge { id: usize },
+    #[error("Person #{id} has none of the identifiers: {identifiers}")]
+    NothingToSplit { id: usize, identifiers: String },
+    #[error("People store error: {message}")]
+    Storage { message: String },
+}
+
+/// An identifier for a person within one source.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PersonHandle {
+    /// Where the handle comes from: `contacts`, `relationships`, or a channel
+    /// type such as `slack`, `email` or `imessage`.
+    pub source: String,
+    /// Identifier within the source (user ID, address, phone number or name).
+    pub id: String,
+}
+
+impl PersonHandle {
+    pub fn new(source: impl Into<String>, id: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            id: id.into(),
+        }
+    }
+
+    /// Whether `identifier` names this handle, as `id` or `source:id`.
+    fn matches(&self, identifier: &str) -> bool {
+        self.id.eq_ignore_ascii_case(identifier)
+            || identifier
+                .split_once(':')
+                .is_some_and(|(s, i)| s == self.source && i.eq_ignore_ascii_case(&self.id))
+    }
+}
+
+impl std::fmt::Display for PersonHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.source, self.id)
+    }
+}
+
+/// Who initiated an interaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionDirection {
+    /// The person contacted the user.
+    Incoming,
+    /// The user contacted the person, or logged a call or meeting.
+    Outgoing,
+}
+
+/// One entry in a person's timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonInteraction {
+    pub at: DateTime<Utc>,
+    /// Channel or medium, e.g. `slack`, `email`, `call`.
+    pub channel: String,
+    pub direction: InteractionDirection,
+    /// Short excerpt or note.
+    #[serde(default)]
+    pub summary: String,
+    /// Topic from message classification, e.g. `question`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// Whether the message asked something of the user.
+    #[serde(default)]
+    pub needs_reply: bool,
+    /// Handle ID the interaction came through, used when splitting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+}
+
+/// A person merged across sources.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Person {
+    pub id: usize,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub emails: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phones: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub handles: Vec<PersonHandle>,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Oldest first.
+    #[serde(default)]
+    pub interactions: Vec<PersonInteraction>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Person {
+    fn new(id: usize, name: String) -> Self {
+        Self {
+            id,
+            name,
+            emails: Vec::new(),
+            phones: Vec::new(),
+            handles: Vec::new(),
+            notes: String::new(),
+            tags: Vec::new(),
+            interactions: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Most recent interaction in either direction.
+    pub fn last_contacted(&self) -> Option<DateTime<Utc>> {
+        self.interactions.iter().map(|i| i.at).max()
+    }
+
+    /// Channels used, most frequent first.
+    pub fn channels_used(&self) -> Vec<String> {
+        ranked(self.interactions.iter().map(|i| i.channel.as_str()))
+    }
+
+    /// Classification topics seen, most frequent first.
+    pub fn topics(&self) -> Vec<String> {
+        ranked(self.interactions.iter().filter_map(|i| i.topic.as_deref()))
+    }
+
+    /// The oldest incoming message needing a reply that has had no outgoing
+    /// interaction since.
+    pub fn awaiting_reply(&self) -> Option<&PersonInteraction> {
+        let last_out = self
+            .interactions
+            .iter()
+            .filter(|i| i.direction == InteractionDirection::Outgoing)
+            .map(|i| i.at)
+            .max();
+        self.interactions.iter().find(|i| {
+            i.direction == InteractionDirection::Incoming
+                && i.needs_reply
+                && last_out.is_none_or(|out| i.at > out)
+        })
+    }
+
+    /// Whether the person's identifiers rule out being the one observed: an
+    /// observation with an email address, phone number or handle of a kind
+    /// the person already has a different one of. Only checked after those
+    /// identifiers failed to match.
+    fn contradicts(
+        &self,
+        emails: &[String],
+        phones: &[String],
+        handle: Option<&PersonHandle>,
+    ) -> bool {
+        (!emails.is_empty() && !self.emails.is_empty())
+            || (!phones.is_empty() && !self.phones.is_empty())
+            || handle.is_some_and(|h| self.handles.iter().any(|own| own.source == h.source))
+    }
+
+    /// Whether the name was derived from an identifier rather than given.
+    fn has_placeholder_name(&self) -> bool {
+        self.name == "Unknown"
+            || self.emails.contains(&self.name)
+            || self.phones.contains(&self.name)
+            || self
+                .handles
+                .iter()
+                .any(|h| h.id == self.name && !NAMED_SOURCES.contains(&h.source.as_str()))
+    }
+
+    /// Whether the person is known by `identifier` (handle, email or phone).
+    fn has_identifier(&self, identifier: &str) -> bool {
+        self.handles.iter().any(|h| h.matches(identifier))
+            || self
+                .emails
+                .iter()
+                .any(|e| *e == normalize_email(identifier))
+            || normalize_phone(identifier)
+                .is_some_and(|p| self.phones.iter().any(|own| phones_match(own, &p)))
+    }
+
+    fn push_interaction(&mut self, interaction: PersonInteraction) {
+        let pos = self
+            .interactions
+            .iter()
+            .rposition(|i| i.at <= interaction.at)
+            .map_or(0, |p| p + 1);
+        self.interactions.insert(pos, interaction);
+        if self.interactions.len() > MAX_INTERACTIONS {
+            let excess = self.interactions.len() - MAX_INTERACTIONS;
+            self.interactions.drain(..excess);
+        }
+    }
+}
+
+/// What is known about a person from one sighting.
+#[derive(Debug, Clone, Default)]
+pub struct PersonObservation {
+    pub name: Option<String>,
+    pub emails: Vec<String>,
+    pub phones: Vec<String>,
+    pub handle: Option<PersonHandle>,
+}
+
+impl PersonObservation {
+    /// An observation of a source handle. Email addresses used as handle IDs,
+    /// and phone numbers on phone-based channels, are also recorded as such.
+    pub fn from_handle(source: impl Into<String>, id: impl Into<String>) -> Self {
+        let handle = PersonHandle::new(source, id);
+        let mut obs = Self::default();
+        if looks_like_email(&handle.id) {
+            obs.emails.push(handle.id.clone());
+        } else if PHONE_SOURCES.contains(&handle.source.as_str())
+            && normalize_phone(&handle.id).is_some()
+        {
+            obs.phones.push(handle.id.clone());
+        }
+        obs.handle = Some(handle);
+        obs
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        if !name.trim().is_empty() {
+            self.name = Some(name.trim().to_string());
+        }
+        self
+    }
+
+    pub fn with_email(mut self, email: impl Into<String>) -> Self {
+        self.emails.push(email.into());
+        self
+    }
+
+    pub fn with_phone(mut self, phone: impl Into<String>) -> Self {
+        self.phones.push(phone.into());
+        self
+    }
+}
+
+/// Thresholds for relationship-maintenance suggestions.
+#[derive(Debug, Clone)]
+pub struct SuggestionConfig {
+    /// Days an unanswered question may wait before it is suggested.
+    pub reply_after_days: i64,
+    /// Days without contact after which a regular contact is suggested.
+    pub stale_after_days: i64,
+    /// Interactions needed before someone counts as a regular contact.
+    pub min_interactions: usize,
+}
+
+impl Default for SuggestionConfig {
+    fn default() -> Self {
+        Self {
+            reply_after_days: 3,
+            stale_after_days: 30,
+            min_interactions: 3,
+        }
+    }
+}
+
+/// Kind of relationship-maintenance suggestion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    /// A message asking something is still unanswered.
+    Reply,
+    /// A regular contact has gone quiet.
+    Reconnect,
+}
+
+/// A suggestion to get back in touch with someone.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelationshipSuggestion {
+    pub person_id: usize,
+    pub name: String,
+    pub kind: SuggestionKind,
+    /// When the unanswered message arrived, or the last contact.
+    pub since: DateTime<Utc>,
+    pub message: String,
+}
+
+/// All known people in a workspace.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PersonGraph {
+    pub people: Vec<Person>,
+    #[serde(default)]
+    next_id: usize,
+}
+
+impl PersonGraph {
+    /// Location of the graph file in a workspace.
+    pub fn path(workspace: &Path) -> PathBuf {
+        workspace.join(".rustant").join("people").join("graph.json")
+    }
+
+    /// Load the workspace graph; a missing or unreadable file yields an empty graph.
+    pub fn load(workspace: &Path) -> Self {
+        std::fs::read_to_string(Self::path(workspace))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist the graph (atomic write).
+    pub fn save(&self, workspace: &Path) -> Result<(), PeopleError> {
+        let path = Self::path(workspace);
+        let storage = |e: std::io::Error| PeopleError::Storage {
+            message: format!("{}: {}", path.display(), e),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(storage)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| PeopleError::Storage {
+            message: e.to_string(),
+        })?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(storage)?;
+        std::fs::rename(&tmp, &path).map_err(storage)?;
+        Ok(())
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Person> {
+        self.people.iter().find(|p| p.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut Person> {
+        self.people.iter_mut().find(|p| p.id == id)
+    }
+
+    /// People whose id (`#3` or `3`), name, email, phone or handle matches `query`.
+    pub fn find(&self, query: &str) -> Vec<&Person> {
+        let query = query.trim();
+        if let Ok(id) = query.trim_start_matches('#').parse::<usize>() {
+            return self.get(id).into_iter().collect();
+        }
+        let lower = query.to_lowercase();
+        self.people
+            .iter()
+            .filter(|p| {
+                p.name.to_lowercase().contains(&lower)
+                    || p.emails.iter().any(|e| e.contains(&lower))
+                    || p.handles
+                        .iter()
+                        .any(|h| h.id.to_lowercase().contains(&lower))
+                    || p.has_identifier(query)
+            })
+            .collect()
+    }
+
+    /// Resolve an observation to a person, creating one if nothing matches,
+    /// and record any new identifiers on it. Returns the person's id.
+    ///
+    /// Matching tries the handle, then email addresses, then phone numbers,
+    /// then the name when exactly one person has it and none of their
+    /// identifiers contradict the observation. Identifiers that already
+    /// belong to someone else are not copied, so a wrong match never pulls
+    /// two people together; use [`merge`](Self::merge) for that.
+    pub fn observe(&mut self, obs: PersonObservation) -> usize {
+        let emails: Vec<String> = obs.emails.iter().map(|e| normalize_email(e)).collect();
+        let phones: Vec<String> = obs
+            .phones
+            .iter()
+            .filter_map(|p| normalize_phone(p))
+            .collect();
+
+        let matched = obs
+            .handle
+            .as_ref()
+            .and_then(|h| self.people.iter().find(|p| p.handles.contains(h)))
+            .or_else(|| {
+                self.people
+                    .iter()
+                    .find(|p| p.emails.iter().any(|e| emails.contains(e)))
+            })
+            .or_else(|| {
+                self.people.iter().find(|p| {
+                    p.phones
+                        .iter()
+                        .any(|own| phones.iter().any(|ph| phones_match(own, ph)))
+                })
+            })
+            .or_else(|| {
+                let name = normalize_name(obs.name.as_deref()?);
+                let mut named = self.people.iter().filter(|p| {
+                    normalize_name(&p.name) == name
+                        && !p.contradicts(&emails, &phones, obs.handle.as_ref())
+                });
+                let first = named.next()?;
+                named.next().is_none().then_some(first)
+            })
+            .map(|p| p.id);
+
+        let id = match matched {
+            Some(id) => id,
+            None => {
+                let name = obs
+                    .name
+                    .clone()
+                    .or_else(|| obs.handle.as_ref().map(|h| h.id.clone()))
+                    .or_else(|| emails.first().cloned())
+                    .or_else(|| phones.first().cloned())
+                    .unwrap_or_else(|| "Unknown".to_string());
+                let id = self.next_free_id();
+                self.next_id = id;
+                self.people.push(Person::new(id, name));
+                id
+            }
+        };
+
+        self.attach(id, obs);
+        id
+    }
+
+    /// Add an observation's identifiers to a known person. Returns the ids
+    /// of other people that already own some of them; those identifiers are
+    /// left where they are.
+    pub fn link(&mut self, id: usize, obs: PersonObservation) -> Result<Vec<usize>, PeopleError> {
+        self.get(id).ok_or(PeopleError::NotFound { id })?;
+        Ok(self.attach(id, obs))
+    }
+
+    fn attach(&mut self, id: usize, obs: PersonObservation) -> Vec<usize> {
+        let mut owners = Vec::new();
+        let mut unowned = |owner: Option<usize>| match owner {
+            Some(owner) => {
+                if !owners.contains(&owner) {
+                    owners.push(owner);
+                }
+                false
+            }
+            None => true,
+        };
+        let emails: Vec<String> = obs
+            .emails
+            .iter()
+            .map(|e| normalize_email(e))
+            .filter(|e| unowned(self.owner_other_than(id, |p| p.emails.contains(e))))
+            .collect();
+        let phones: Vec<String> = obs
+            .phones
+            .iter()
+            .filter_map(|p| normalize_phone(p))
+            .filter(|ph| {
+                unowned(
+                    self.owner_other_than(id, |p| p.phones.iter().any(|own| phones_match(own, ph))),
+                )
+            })
+            .collect();
+        let handle = obs
+            .handle
+            .filter(|h| unowned(self.owner_other_than(id, |p| p.handles.contains(h))));
+
+        let Some(person) = self.get_mut(id) else {
+            return owners;
+        };
+        // A real name beats one derived from an identifier.
+        let placeholder = person.has_placeholder_name();
+        for email in emails {
+            if !person.emails.contains(&email) {
+                person.emails.push(email);
+            }
+        }
+        for phone in phones {
+            if !person.phones.iter().any(|own| phones_match(own, &phone)) {
+                person.phones.push(phone);
+            }
+        }
+        if let Some(handle) = handle
+            && !person.handles.contains(&handle)
+        {
+            person.handles.push(handle);
+        }
+        if let Some(name) = obs.name
+            && placeholder
+        {
+            person.name = name;
+        }
+        owners
+    }
+
+    fn next_free_id(&self) -> usize {
+        let highest = self.people.iter().map(|p| p.id).max().unwrap_or(0);
+        self.next_id.max(highest) + 1
+    }
+
+    fn owner_other_than(&self, id: usize, check: impl Fn(&Person) -> bool) -> Option<usize> {
+        self.people
+            .iter()
+            .find(|p| p.id != id && check(p))
+            .map(|p| p.id)
+    }
+
+    /// Add an interaction to a person's timeline.
+    pub fn record_interaction(
+        &mut self,
+        id: usize,
+        interaction: PersonInteraction,
+    ) -> Result<(), PeopleError> {
+        let person = self.get_mut(id).ok_or(PeopleError::NotFound { id })?;
+        person.push_interaction(interaction);
+        Ok(())
+    }
+
+    /// Record a message received on a channel, resolving its sender.
+    /// Returns the sender's person id.
+    pub fn record_incoming(
+        &mut self,
+        obs: PersonObservation,
+        at: DateTime<Utc>,
+        text: &str,
+        topic: Option<String>,
+        needs_reply: bool,
+    ) -> usize {
+        let channel = obs
+            .handle
+            .as_ref()
+            .map(|h| h.source.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        let handle = obs.handle.as_ref().map(|h| h.id.clone());
+        let id = self.observe(obs);
+        let _ = self.record_interaction(
+            id,
+            PersonInteraction {
+                at,
+                channel,
+                direction: InteractionDirection::Incoming,
+                summary: summarize(text),
+                topic,
+                needs_reply,
+                handle,
+            },
+        );
+        id
+    }
+
+    /// Record a message the user sent to a channel handle.
+    pub fn record_outgoing(
+        &mut self,
+        handle: PersonHandle,
+        at: DateTime<Utc>,
+        text: &str,
+    ) -> usize {
+        let channel = handle.source.clone();
+        let handle_id = handle.id.clone();
+        let id = self.observe(PersonObservation::from_handle(handle.source, handle.id));
+        let _ = self.record_interaction(
+            id,
+            PersonInteraction {
+                at,
+                channel,
+                direction: InteractionDirection::Outgoing,
+                summary: summarize(text),
+                topic: None,
+                needs_reply: false,
+                handle: Some(handle_id),
+            },
+        );
+        id
+    }
+
+    /// Record a classified channel message from its sender.
+    pub fn record_classified(&mut self, classified: &ClassifiedMessage) -> usize {
+        let msg = &classified.original;
+        let needs_reply = matches!(
+            classified.message_type,
+            MessageType::Question | MessageType::ActionRequired
+        );
+        let topic = match classified.message_type {
+            MessageType::Question => "question",
+            MessageType::ActionRequired => "action_required",
+            MessageType::Notification => "notification",
+            MessageType::Greeting => "greeting",
+            MessageType::Command => "command",
+            MessageType::FollowUp => "follow_up",
+            MessageType::Spam => "spam",
+        };
+        self.record_incoming(
+            observation_for(msg),
+            msg.timestamp,
+            msg.content.as_text().unwrap_or_default(),
+            Some(topic.to_string()),
+            needs_reply,
+        )
+    }
+
+    /// Fold `other` into `keep`: identifiers, tags, notes and timelines are
+    /// combined and `other` is removed.
+    pub fn merge(&mut self, keep: usize, other: usize) -> Result<(), PeopleError> {
+        if keep == other {
+            return Err(PeopleError::SelfMerge { id: keep });
+        }
+        self.get(keep).ok_or(PeopleError::NotFound { id: keep })?;
+        let pos = self
+            .people
+            .iter()
+            .position(|p| p.id == other)
+            .ok_or(PeopleError::NotFound { id: other })?;
+        let absorbed = self.people.remove(pos);
+        let person = self.get_mut(keep).expect("checked above");
+        for email in absorbed.emails {
+            if !person.emails.contains(&email) {
+                person.emails.push(email);
+            }
+        }
+        for phone in absorbed.phones {
+            if !person.phones.iter().any(|own| phones_match(own, &phone)) {
+                person.phones.push(phone);
+            }
+        }
+        for handle in absorbed.handles {
+            if !person.handles.contains(&handle) {
+                person.handles.push(handle);
+            }
+        }
+        for tag in absorbed.tags {
+            if !person.tags.contains(&tag) {
+                person.tags.push(tag);
+            }
+        }
+        if !absorbed.notes.is_empty() {
+            if !person.notes.is_empty() {
+                person.notes.push('\n');
+            }
+            person.notes.push_str(&absorbed.notes);
+        }
+        for interaction in absorbed.interactions {
+            person.push_interaction(interaction);
+        }
+        person.created_at = person.created_at.min(absorbed.created_at);
+        Ok(())
+    }
+
+    /// Move the given identifiers (handle IDs, `source:id`, emails or phone
+    /// numbers), and the interactions that came through them, from `id` to a
+    /// new person. Returns the new person's id.
+    pub fn split(
+        &mut self,
+        id: usize,
+        identifiers: &[String],
+        name: Option<&str>,
+    ) -> Result<usize, PeopleError> {
+        let new_id = self.next_free_id();
+        let person = self.get_mut(id).ok_or(PeopleError::NotFound { id })?;
+        let moved = |ident: &str| identifiers.iter().any(|i| i.eq_ignore_ascii_case(ident));
+
+        let mut split = Person::new(new_id, name.unwrap_or(person.name.as_str()).to_string());
+        let (handles, kept): (Vec<_>, Vec<_>) = person
+            .handles
+            .drain(..)
+            .partition(|h| identifiers.iter().any(|i| h.matches(i)));
+        person.handles = kept;
+        split.handles = handles;
+        let (emails, kept): (Vec<_>, Vec<_>) = person.emails.drain(..).partition(|e| moved(e));
+        person.emails = kept;
+        split.emails = emails;
+        let wanted: Vec<String> = identifiers
+            .iter()
+            .filter_map(|i| normalize_phone(i))
+            .collect();
+        let (phones, kept): (Vec<_>, Vec<_>) = person
+            .phones
+            .drain(..)
+            .partition(|p| wanted.iter().any(|w| phones_match(p, w)));
+        person.phones = kept;
+        split.phones = phones;
+
+        if split.handles.is_empty() && split.emails.is_empty() && split.phones.is_empty() {
+            return Err(PeopleError::NothingToSplit {
+                id,
+                identifiers: identifiers.join(", "),
+            });
+        }
+        let moved_ids: Vec<String> = split
+            .handles
+            .iter()
+            .map(|h| h.id.clone())
+            .chain(split.emails.iter().cloned())
+            .chain(split.phones.iter().cloned())
+            .collect();
+        let (interactions, kept): (Vec<_>, Vec<_>) = person.interactions.drain(..).partition(|i| {
+            i.handle.as_deref().is_some_and(|h| {
+                moved_ids.iter().any(|m| m.eq_ignore_ascii_case(h))
+                    || normalize_phone(h)
+                        .is_some_and(|n| split.phones.iter().any(|p| phones_match(p, &n)))
+            })
+        });
+        person.interactions = kept;
+        split.interactions = interactions;
+
+        self.next_id = new_id;
+        self.people.push(split);
+        Ok(new_id)
+    }
+
+    /// Remove a person and everything recorded about them.
+    pub fn forget(&mut self, id: usize) -> Result<Person, PeopleError> {
+        let pos = self
+            .people
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or(PeopleError::NotFound { id })?;
+        Ok(self.people.remove(pos))
+    }
+
+    /// Everything recorded about one person, as JSON.
+    pub fn export(&self, id: usize) -> Result<serde_json::Value, PeopleError> {
+        let person = self.get(id).ok_or(PeopleError::NotFound { id })?;
+        serde_json::to_value(person).map_err(|e| PeopleError::Storage {
+            message: e.to_string(),
+        })
+    }
+
+    /// Relationship-maintenance suggestions at `now`: unanswered questions
+    /// first, then regular contacts gone quiet, oldest first within each kind.
+    pub fn suggestions(
+        &self,
+        now: DateTime<Utc>,
+        config: &SuggestionConfig,
+    ) -> Vec<RelationshipSuggestion> {
+        let mut out = Vec::new();
+        for person in &self.people {
+            if let Some(pending) = person.awaiting_reply()
+                && now - pending.at >= Duration::days(config.reply_after_days)
+            {
+                let mut message = format!(
+                    "You haven't replied to {} in {}",
+                    person.name,
+                    humanize_age(now - pending.at)
+                );
+                if !pending.summary.is_empty() {
+                    message.push_str(&format!(" — they asked: \"{}\"", pending.summary));
+                }
+                out.push(RelationshipSuggestion {
+                    person_id: person.id,
+                    name: person.name.clone(),
+                    kind: SuggestionKind::Reply,
+                    since: pending.at,
+                    message,
+                });
+                continue;
+            }
+            if person.interactions.len() >= config.min_interactions
+                && let Some(last) = person.last_contacted()
+                && now - last >= Duration::days(config.stale_after_days)
+            {
+                let mut message = format!(
+                    "You haven't been in touch with {} in {}",
+                    person.name,
+                    humanize_age(now - last)
+                );
+                let channels = person.channels_used();
+                if !channels.is_empty() {
+                    message.push_str(&format!(" (usually via {})", channels.join(", ")));
+                }
+                out.push(RelationshipSuggestion {
+                    person_id: person.id,
+                    name: person.name.clone(),
+                    kind: SuggestionKind::Reconnect,
+                    since: last,
+                    message,
+                });
+            }
+        }
+        out.sort_by_key(|s| (s.kind == SuggestionKind::Reconnect, s.since));
+        out
+    }
+}
+
+/// An observation of a channel message's sender.
+pub fn observation_for(msg: &ChannelMessage) -> PersonObservation {
+    let mut obs = PersonObservation::from_handle(msg.channel_type.to_string(), &msg.sender.id);
+    if let Some(name) = &msg.sender.display_name {
+        obs = obs.with_name(name);
+    }
+    if msg.channel_type == ChannelType::Email
+        && let Some(from) = msg.metadata.get("from")
+    {
+        obs = obs.with_email(from.clone());
+    }
+    obs
+}
+
+fn summarize(text: &str) -> String {
+    let line = text
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("")
+        .trim();
+    if line.chars().count() > SUMMARY_CHARS {
+        let cut: String = line.chars().take(SUMMARY_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+fn ranked<'a>(items: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let mut order = Vec::new();
+    for item in items {
+        let count = counts.entry(item).or_insert(0);
+        if *count == 0 {
+            order.push(item);
+        }
+        *count += 1;
+    }
+    order.sort_by_key(|item| std::cmp::Reverse(counts[item]));
+    order.into_iter().map(str::to_string).collect()
+}
+
+fn humanize_age(age: Duration) -> String {
+    let days = age.num_days();
+    match days {
+        0 => "less than a day".to_string(),
+        1 => "1 day".to_string(),
+        2..=13 => format!("{} days", days),
+        14..=59 => format!("{} weeks", days / 7),
+        _ => format!("{} months", days / 30),
+    }
+}
+
+fn looks_like_email(id: &str) -> bool {
+    id.split_once('@')
+        .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'))
+}
+
+fn normalize_email(email: &str) -> String {
+    email
+        .trim()
+        .trim_start_matches("mailto:")
+        .trim_matches(|c| c == '<' || c == '>')
+        .to_lowercase()
+}
+
+/// Digits of a phone number, keeping a leading `+`; `None` if it has fewer
+/// than 7 digits or contains letters.
+fn normalize_phone(phone: &str) -> Option<String> {
+    if phone.chars().any(|c| c.is_alphabetic() || c == '@') {
+        return None;
+    }
+    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+    if digits.len() < 7 {
+        return None;
+    }
+    Some(if phone.trim_start().starts_with('+') {
+        format!("+{}", digits)
+    } else {
+        digits
+    })
+}
+
+/// Numbers match when their last ten digits agree, so `+1 555 010 0199`
+/// matches `(555) 010-0199`.
+fn phones_match(a: &str, b: &str) -> bool {
+    let tail = |s: &str| {
+        let digits: Vec<char> = s.chars().filter(|c| c.is_ascii_digit()).collect();
+        let start = digits.len().saturating_sub(10);
+        digits[start..].iter().collect::<String>()
+    };
+    tail(a) == tail(b)
+}
+
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::ChannelUser;
+
+    fn message(
+        graph: &mut PersonGraph,
+        source: &str,
+        id: &str,
+        name: &str,
+        days_ago: i64,
+    ) -> usize {
+        graph.record_incoming(
+            PersonObservation::from_handle(source, id).with_name(name),
+            Utc::now() - Duration::days(days_ago),
+            "Did you get a chance to look at the report?",
+            Some("question".into()),
+            true,
+        )
+    }
+
+    #[test]
+    fn test_observe_merges_across_sources() {
+        let mut graph = PersonGraph::default();
+        let card = graph.observe(
+            PersonObservation::from_handle("contacts", "Priya Shah")
+                .with_name("Priya Shah")
+                .with_email("Priya@Example.com")
+                .with_phone("+1 (555) 010-0199"),
+        );
+        let email = graph.observe(PersonObservation::from_handle("email", "priya@example.com"));
+        let imessage = graph.observe(PersonObservation::from_handle("imessage", "5550100199"));
+        let slack =
+            graph.observe(PersonObservation::from_handle("slack", "U123").with_name("priya  shah"));
+        assert_eq!(email, card);
+        assert_eq!(imessage, card);
+        assert_eq!(slack, card);
+        assert_eq!(graph.people.len(), 1);
+        assert_eq!(graph.get(card).unwrap().handles.len(), 4);
+
+        // Conflicting identifiers or an ambiguous name do not auto-match.
+        graph.observe(
+            PersonObservation::default()
+                .with_name("Sam")
+                .with_email("sam@a.io"),
+        );
+        graph.observe(
+            PersonObservation::default()
+                .with_name("Sam")
+                .with_email("sam@b.io"),
+        );
+        let third = graph.observe(PersonObservation::from_handle("slack", "U9").with_name("Sam"));
+        assert_eq!(graph.find("Sam").len(), 3);
+        assert_eq!(graph.get(third).unwrap().handles[0].id, "U9");
+    }
+
+    #[test]
+    fn test_merge_and_split() {
+        let mut graph = PersonGraph::default();
+        let a = message(&mut graph, "slack", "U1", "Alex", 2);
+        let b = message(&mut graph, "email", "alex@corp.com", "Alex Kim", 1);
+        assert_ne!(a, b);
+
+        graph.merge(a, b).unwrap();
+        assert_eq!(graph.people.len(), 1);
+        let alex = graph.get(a).unwrap();
+        assert_eq!(alex.interactions.len(), 2);
+        assert_eq!(alex.channels_used().len(), 2);
+        assert!(matches!(
+            graph.merge(a, a),
+            Err(PeopleError::SelfMerge { .. })
+        ));
+
+        let split = graph
+            .split(a, &["alex@corp.com".into()], Some("Alex Kim"))
+            .unwrap();
+        assert_eq!(graph.get(a).unwrap().interactions.len(), 1);
+        let moved = graph.get(split).unwrap();
+        assert_eq!(moved.emails, vec!["alex@corp.com".to_string()]);
+        assert_eq!(moved.interactions.len(), 1);
+        // The moved address now resolves to the split person.
+        assert_eq!(
+            graph.observe(PersonObservation::from_handle("email", "alex@corp.com")),
+            split
+        );
+        assert!(matches!(
+            graph.split(a, &["nobody".into()], None),
+            Err(PeopleError::NothingToSplit { .. })
+        ));
+    }
+
+    #[test]
+    fn test_suggestions_reply_and_reconnect() {
+        let mut graph = PersonGraph::default();
+        let priya = message(&mut graph, "slack", "U1", "Priya", 21);
+        let sam =
+            graph.observe(PersonObservation::from_handle("email", "sam@x.io").with_name("Sam"));
+        for days in [60, 50, 45] {
+            graph
+                .record_interaction(
+                    sam,
+                    PersonInteraction {
+                        at: Utc::now() - Duration::days(days),
+                        channel: "email".into(),
+                        direction: InteractionDirection::Outgoing,
+                        summary: String::new(),
+                        topic: None,
+                        needs_reply: false,
+                        handle: None,
+                    },
+                )
+                .unwrap();
+        }
+        let fresh = message(&mut graph, "slack", "U2", "Jo", 1);
+
+        let suggestions = graph.suggestions(Utc::now(), &SuggestionConfig::default());
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].person_id, priya);
+        assert_eq!(suggestions[0].kind, SuggestionKind::Reply);
+        assert!(suggestions[0].message.contains("3 weeks"));
+        assert!(suggestions[0].message.contains("the report"));
+        assert_eq!(suggestions[1].person_id, sam);
+        assert_eq!(suggestions[1].kind, SuggestionKind::Reconnect);
+        assert!(suggestions.iter().all(|s| s.person_id != fresh));
+
+        // Replying clears the reply suggestion.
+        graph.record_outgoing(PersonHandle::new("slack", "U1"), Utc::now(), "Sent it!");
+        let suggestions = graph.suggestions(Utc::now(), &SuggestionConfig::default());
+        assert!(suggestions.iter().all(|s| s.person_id != priya));
+    }
+
+    #[test]
+    fn test_record_classified_forget_export_and_persistence() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut graph = PersonGraph::default();
+        let sender = ChannelUser::new("U7", ChannelType::Slack).with_name("Lee");
+        let msg = ChannelMessage::text(ChannelType::Slack, "general", sender, "Can you review?");
+        let classified = crate::channels::MessageClassifier::new(Default::default()).classify(&msg);
+        let lee = graph.record_classified(&classified);
+        graph.save(dir.path()).unwrap();
+
+        let mut loaded = PersonGraph::load(dir.path());
+        let exported = loaded.export(lee).unwrap();
+        assert_eq!(exported["name"], "Lee");
+        assert_eq!(exported["interactions"][0]["channel"], "slack");
+        loaded.forget(lee).unwrap();
+        assert!(loaded.get(lee).is_none());
+        assert!(matches!(
+            loaded.export(lee),
+            Err(PeopleError::NotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,346 @@
+//! Agent personas: switchable tool, tone, and approval profiles.
+//!
+//! A persona bundles a system prompt fragment, a tool allow/deny policy, an
+//! optional approval mode override, and an optional preferred model. The
+//! active persona filters the tool definitions sent to the LLM, so disallowed
+//! tools are never visible to the model, and calls to them are refused.
+//!
+//! Two built-in personas ship by default (`ops-sre` and `writing-assistant`);
+//! personas defined under `[[personas]]` in config.toml override built-ins
+//! with the same name.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::config::ApprovalMode;
+
+/// Named tool groups usable in `tool_groups`. Entries ending in `*` match by prefix.
+const TOOL_GROUPS: &[(&str, &[&str])] = &[
+    ("core", &["ask_user", "echo", "datetime", "calculator"]),
+    (
+        "files",
+        &[
+            "file_read",
+            "file_list",
+            "file_search",
+            "file_write",
+            "file_patch",
+            "smart_edit",
+            "codebase_search",
+            "document_read",
+            "file_organizer",
+            "compress",
+        ],
+    ),
+    ("git", &["git_status", "git_diff", "git_commit"]),
+    ("shell", &["shell_exec"]),
+    ("web", &["web_search", "web_fetch", "http_api"]),
+    ("browser", &["browser_*"]),
+    (
+        "infra",
+        &[
+            "shell_exec",
+            "system_monitor",
+            "incident",
+            "http_api",
+            "file_read",
+            "file_list",
+            "file_search",
+            "git_status",
+            "git_diff",
+            "compress",
+        ],
+    ),
+    (
+        "content",
+        &[
+            "content_engine",
+            "template",
+            "pdf_generate",
+            "document_read",
+            "knowledge_graph",
+            "file_read",
+            "file_list",
+            "file_write",
+        ],
+    ),
+    ("macos", &["macos_*", "imessage_*", "homekit"]),
+    (
+        "productivity",
+        &[
+            "pomodoro",
+            "inbox",
+            "life_planner",
+            "relationships",
+            "finance",
+            "travel",
+            "flashcards",
+            "briefing",
+        ],
+    ),
+];
+
+/// Tools every persona keeps, so the agent can always ask for clarification.
+const ALWAYS_ALLOWED: &[&str] = &["ask_user"];
+
+/// Errors from persona lookup and validation.
+#[derive(Debug, thiserror::Error)]
+pub enum PersonaError {
+    #[error("Unknown persona '{name}'. Available: {available}")]
+    Unknown { name: String, available: String },
+    #[error("Persona '{persona}' uses unknown tool group '{group}'. Available groups: {available}")]
+    UnknownToolGroup {
+        persona: String,
+        group: String,
+        available: String,
+    },
+}
+
+/// A persona definition, as written under `[[personas]]` in config.toml.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonaConfig {
+    /// Persona name used with `/persona <name>` and `--persona`.
+    pub name: String,
+    /// Short description shown in `/persona list`.
+    #[serde(default)]
+    pub description: String,
+    /// Fragment appended to the system prompt while the persona is active.
+    #[serde(default)]
+    pub prompt: String,
+    /// Named tool groups the persona may use (see [`tool_group_names`]).
+    #[serde(default)]
+    pub tool_groups: Vec<String>,
+    /// Additional tools the persona may use. Entries ending in `*` match by prefix.
+    #[serde(default)]
+    pub allow_tools: Vec<String>,
+    /// Tools the persona may never use; takes precedence over allows.
+    #[serde(default)]
+    pub deny_tools: Vec<String>,
+    /// Approval mode to use while the persona is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_mode: Option<ApprovalMode>,
+    /// Preferred model while the persona is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+fn pattern_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+/// Names of the built-in tool groups.
+pub fn tool_group_names() -> Vec<&'static str> {
+    TOOL_GROUPS.iter().map(|(name, _)| *name).collect()
+}
+
+fn group_patterns(group: &str) -> &'static [&'static str] {
+    TOOL_GROUPS
+        .iter()
+        .find(|(name, _)| *name == group)
+        .map(|(_, tools)| *tools)
+        .unwrap_or(&[])
+}
+
+impl PersonaConfig {
+    /// Whether the persona restricts tools to an allow-list.
+    fn has_allow_list(&self) -> bool {
+        !self.tool_groups.is_empty() || !self.allow_tools.is_empty()
+    }
+
+    /// Whether a tool may be offered to and executed by the model.
+    ///
+    /// Deny entries always win. Without any groups or allow entries every
+    /// non-denied tool is allowed.
+    pub fn allows_tool(&self, tool: &str) -> bool {
+        if ALWAYS_ALLOWED.contains(&tool) {
+            return true;
+        }
+        if self.deny_tools.iter().any(|p| pattern_matches(p, tool)) {
+            return false;
+        }
+        if !self.has_allow_list() {
+            return true;
+        }
+        self.allow_tools.iter().any(|p| pattern_matches(p, tool))
+            || self
+                .tool_groups
+                .iter()
+                .any(|g| group_patterns(g).iter().any(|p| pattern_matches(p, tool)))
+    }
+
+    /// Build the system prompt fragment for this persona.
+    pub fn prompt_fragment(&self) -> String {
+        let mut fragment = format!("\n\n## Active persona: {}\n", self.name);
+        if !self.prompt.is_empty() {
+            fragment.push_str(self.prompt.trim());
+            fragment.push('\n');
+        }
+        fragment
+    }
+
+    /// Tool groups referenced by this persona that do not exist.
+    pub fn unknown_groups(&self) -> Vec<&str> {
+        self.tool_groups
+            .iter()
+            .filter(|g| !TOOL_GROUPS.iter().any(|(name, _)| name == g))
+            .map(|g| g.as_str())
+            .collect()
+    }
+}
+
+/// Built-in personas available without configuration.
+pub fn builtin_personas() -> Vec<PersonaConfig> {
+    vec![
+        PersonaConfig {
+            name: "ops-sre".into(),
+            description: "Terse infrastructure operator with paranoid approvals".into(),
+            prompt: "You are acting as a site reliability engineer. Be terse: lead with the \
+                     command or finding, skip pleasantries. Prefer read-only inspection before \
+                     any change, state the blast radius of every mutating command, and never \
+                     run destructive operations without an explicit rollback plan."
+                .into(),
+            tool_groups: vec!["core".into(), "infra".into(), "git".into()],
+            allow_tools: Vec::new(),
+            deny_tools: Vec::new(),
+            approval_mode: Some(ApprovalMode::Paranoid),
+            model: None,
+        },
+        PersonaConfig {
+            name: "writing-assistant".into(),
+            description: "Warm writing partner limited to content tools, no shell".into(),
+            prompt: "You are acting as a writing assistant. Use a warm, encouraging tone, \
+                     explain your editorial suggestions, and preserve the author's voice."
+                .into(),
+            tool_groups: vec!["core".into(), "content".into(), "web".into()],
+            allow_tools: Vec::new(),
+            deny_tools: vec!["shell_exec".into(), "http_api".into()],
+            approval_mode: None,
+            model: None,
+        },
+    ]
+}
+
+/// All personas known to this configuration, keyed by name.
+#[derive(Debug, Clone, Default)]
+pub struct PersonaRegistry {
+    personas: BTreeMap<String, PersonaConfig>,
+}
+
+impl PersonaRegistry {
+    /// Build a registry from built-ins overlaid with configured personas.
+    pub fn from_config(configured: &[PersonaConfig]) -> Self {
+        let mut personas = BTreeMap::new();
+        for persona in builtin_personas()
+            .into_iter()
+            .chain(configured.iter().cloned())
+        {
+            personas.insert(persona.name.clone(), persona);
+        }
+        Self { personas }
+    }
+
+    /// Look up a persona by name.
+    ///
+    /// A persona naming a tool group that does not exist is rejected rather
+    /// than activated with a partial toolset.
+    pub fn get(&self, name: &str) -> Result<&PersonaConfig, PersonaError> {
+        let persona = self
+            .personas
+            .get(name)
+            .ok_or_else(|| PersonaError::Unknown {
+                name: name.to_string(),
+                available: self.names().join(", "),
+            })?;
+        if let Some(group) = persona.unknown_groups().first() {
+            return Err(PersonaError::UnknownToolGroup {
+                persona: persona.name.clone(),
+                group: group.to_string(),
+                available: tool_group_names().join(", "),
+            });
+        }
+        Ok(persona)
+    }
+
+    /// Sorted persona names.
+    pub fn names(&self) -> Vec<&str> {
+        self.personas.keys().map(|k| k.as_str()).collect()
+    }
+
+    /// Iterate personas in name order.
+    pub fn iter(&self) -> impl Iterator<Item = &PersonaConfig> {
+        self.personas.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_tool_policies() {
+        let registry = PersonaRegistry::from_config(&[]);
+        let sre = registry.get("ops-sre").unwrap();
+        assert!(sre.allows_tool("shell_exec"));
+        assert!(sre.allows_tool("git_diff"));
+        assert!(sre.allows_tool("ask_user"));
+        assert!(!sre.allows_tool("content_engine"));
+        assert_eq!(sre.approval_mode, Some(ApprovalMode::Paranoid));
+
+        let writer = registry.get("writing-assistant").unwrap();
+        assert!(writer.allows_tool("content_engine"));
+        assert!(writer.allows_tool("web_search"));
+        assert!(!writer.allows_tool("shell_exec"));
+        assert!(!writer.allows_tool("http_api"));
+    }
+
+    #[test]
+    fn test_configured_persona_overrides_and_wildcards() {
+        let custom = PersonaConfig {
+            name: "ops-sre".into(),
+            description: String::new(),
+            prompt: "Custom".into(),
+            tool_groups: vec!["browser".into()],
+            allow_tools: vec!["git_*".into()],
+            deny_tools: vec!["browser_js_eval".into()],
+            approval_mode: None,
+            model: Some("gpt-4o-mini".into()),
+        };
+        let registry = PersonaRegistry::from_config(&[custom]);
+        let sre = registry.get("ops-sre").unwrap();
+        assert!(sre.allows_tool("browser_click"));
+        assert!(sre.allows_tool("git_commit"));
+        assert!(!sre.allows_tool("browser_js_eval"));
+        assert!(!sre.allows_tool("shell_exec"));
+        assert!(sre.prompt_fragment().contains("Custom"));
+        assert_eq!(registry.names(), vec!["ops-sre", "writing-assistant"]);
+    }
+
+    #[test]
+    fn test_deny_only_persona_and_unknown_lookup() {
+        let toml_str = r#"
+            name = "no-shell"
+            deny_tools = ["shell_exec"]
+            tool_groups = ["nonexistent"]
+        "#;
+        let persona: PersonaConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(persona.unknown_groups(), vec!["nonexistent"]);
+        let registry = PersonaRegistry::from_config(std::slice::from_ref(&persona));
+        let err = registry.get("no-shell").unwrap_err().to_string();
+        assert!(err.contains("'nonexistent'"), "{}", err);
+        assert!(err.contains("infra"), "{}", err);
+
+        let open = PersonaConfig {
+            tool_groups: Vec::new(),
+            ..persona
+        };
+        assert!(open.allows_tool("file_write"));
+        assert!(!open.allows_tool("shell_exec"));
+
+        let registry = PersonaRegistry::from_config(&[]);
+        let err = registry.get("pirate").unwrap_err();
+        assert!(err.to_string().contains("ops-sre"));
+    }
+}
@@ -0,0 +1,650 @@
+//! Context pins: files and facts kept in every prompt.
+//!
+//! Pins are rendered into the system prompt rather than the conversation, so
+//! context compression never summarizes them away. File pins are re-read from
+//! disk each time a prompt is assembled and cut to a per-pin token budget; a
+//! pin may name a line range to include only part of a big file. A pinned
+//! file that no longer exists stays pinned and is reported as missing.
+
+use crate::brain::TokenCounter;
+use crate::types::{Content, Message};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Name of the pseudo-tool the model uses to pin context.
+pub const PIN_TOOL: &str = "pin_context";
+
+/// What a pin keeps in context.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PinTarget {
+    /// A file, relative to the workspace unless absolute, optionally limited
+    /// to a 1-based inclusive line range.
+    File {
+        path: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lines: Option<(usize, usize)>,
+    },
+    /// A fact stated as text.
+    Fact { text: String },
+}
+
+impl PinTarget {
+    /// Interpret `/pin` input: an existing file, optionally followed by
+    /// `:start-end`, or otherwise a fact. Input that looks like a path but
+    /// names no file is rejected rather than pinned as a fact.
+    pub fn parse(input: &str, workspace: &Path) -> Result<Self, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err("Nothing to pin".to_string());
+        }
+        let (path, lines) = match input.rsplit_once(':') {
+            Some((path, range)) => match parse_range(range) {
+                Some(lines) => (path, Some(lines)),
+                None => (input, None),
+            },
+            None => (input, None),
+        };
+        if workspace.join(path).is_file() {
+            return Ok(PinTarget::File {
+                path: PathBuf::from(path),
+                lines,
+            });
+        }
+        if !input.contains(char::is_whitespace) && input.contains(['/', '\\']) {
+            return Err(format!("No file named {}", path));
+        }
+        Ok(PinTarget::Fact {
+            text: input.to_string(),
+        })
+    }
+}
+
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    let (start, end) = range.split_once('-')?;
+    let (start, end) = (start.trim().parse().ok()?, end.trim().parse().ok()?);
+    (start >= 1 && end >= start).then_some((start, end))
+}
+
+/// A pinned file or fact.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextPin {
+    #[serde(flatten)]
+    pub target: PinTarget,
+    pub pinned_at: DateTime<Utc>,
+    /// Whether the model pinned it through [`PIN_TOOL`].
+    #[serde(default)]
+    pub by_agent: bool,
+}
+
+impl ContextPin {
+    /// How the pin is shown and matched by `/unpin`: `path`,
+    /// `path:start-end`, or the fact text.
+    pub fn label(&self) -> String {
+        match &self.target {
+            PinTarget::File {
+                path,
+                lines: Some((start, end)),
+            } => format!("{}:{}-{}", path.display(), start, end),
+            PinTarget::File { path, lines: None } => path.display().to_string(),
+            PinTarget::Fact { text } => text.clone(),
+        }
+    }
+}
+
+/// The pins of a session, in the order they were added.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PinSet {
+    pins: Vec<ContextPin>,
+}
+
+impl PinSet {
+    /// Pin `target`. Returns false if it is already pinned.
+    pub fn pin(&mut self, target: PinTarget, by_agent: bool) -> bool {
+        if self.pins.iter().any(|p| p.target == target) {
+            return false;
+        }
+        self.pins.push(ContextPin {
+            target,
+            pinned_at: Utc::now(),
+            by_agent,
+        });
+        true
+    }
+
+    /// Remove the pin matching `key`: its label, a pinned file's path, or
+    /// the start of exactly one pinned fact.
+    pub fn unpin(&mut self, key: &str) -> Option<ContextPin> {
+        let key = key.trim();
+        let exact = self.pins.iter().position(|p| {
+            p.label() == key
+                || matches!(&p.target, PinTarget::File { path, .. } if path == Path::new(key))
+        });
+        let index = exact.or_else(|| {
+            let key = key.to_lowercase();
+            let mut facts = self.pins.iter().enumerate().filter(|(_, p)| {
+                matches!(&p.target, PinTarget::Fact { text } if text.to_lowercase().starts_with(&key))
+            });
+            match (facts.next(), facts.next()) {
+                (Some((i, _)), None) => Some(i),
+                _ => None,
+            }
+        })?;
+        Some(self.pins.remove(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ContextPin> {
+        self.pins.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    /// Render the pins for the system prompt, reading pinned files from
+    /// `workspace` now. Each pin is cut to `budget` tokens.
+    pub fn render(
+        &self,
+        workspace: Option<&Path>,
+        counter: &TokenCounter,
+        budget: usize,
+    ) -> PinnedContext {
+        let mut context = PinnedContext::default();
+        if self.pins.is_empty() {
+            return context;
+        }
+        let mut files = String::new();
+        let mut facts = String::new();
+        for pin in &self.pins {
+            let label = pin.label();
+            let (status, tokens) = match &pin.target {
+                PinTarget::Fact { text } => {
+                    facts.push_str(&format!("- {}\n", text));
+                    (PinStatus::Full, counter.count(text))
+                }
+                PinTarget::File { path, lines } => {
+                    let resolved = resolve(workspace, path);
+                    match std::fs::read(&resolved) {
+                        Ok(bytes) => {
+                            let text = String::from_utf8_lossy(&bytes);
+                            let excerpt = excerpt(&text, *lines, counter, budget);
+                            files.push_str(&format!("### {}\n```\n{}", label, excerpt.text));
+                            if !excerpt.text.is_empty() && !excerpt.text.ends_with('\n') {
+                                files.push('\n');
+                            }
+                            files.push_str("```\n");
+                            let status = if excerpt.from == 1 && excerpt.to == excerpt.total {
+                                context.full_files.push(resolved);
+                                PinStatus::Full
+                            } else {
+                                files.push_str(&format!(
+                                    "(lines {}-{} of {}; pin `{}:<start>-<end>` for another part)\n",
+                                    excerpt.from,
+                                    excerpt.to,
+                                    excerpt.total,
+                                    path.display()
+                                ));
+                                PinStatus::Partial {
+                                    from: excerpt.from,
+                                    to: excerpt.to,
+                                    total: excerpt.total,
+                                }
+                            };
+                            files.push('\n');
+                            (status, excerpt.tokens)
+                        }
+                        Err(_) => {
+                            files.push_str(&format!(
+                                "### {}\n(missing: the file no longer exists)\n\n",
+                                label
+                            ));
+                            (PinStatus::Missing, 0)
+                        }
+                    }
+                }
+            };
+            context.pins.push(RenderedPin {
+                label,
+                tokens,
+                status,
+                by_agent: pin.by_agent,
+            });
+        }
+
+        context.prompt = String::from(
+            "\n\n## Pinned Context\n\
+             The user pinned these files and facts. File contents are current as of this \
+             request and take precedence over earlier tool output.\n",
+        );
+        if !facts.is_empty() {
+            context.prompt.push_str("\n### Facts\n");
+            context.prompt.push_str(&facts);
+        }
+        if !files.is_empty() {
+            context.prompt.push('\n');
+            context.prompt.push_str(files.trim_end());
+            context.prompt.push('\n');
+        }
+        context
+    }
+}
+
+fn resolve(workspace: Option<&Path>, path: &Path) -> PathBuf {
+    match workspace {
+        Some(workspace) => workspace.join(path),
+        None => path.to_path_buf(),
+    }
+}
+
+struct Excerpt {
+    text: String,
+    from: usize,
+    to: usize,
+    total: usize,
+    tokens: usize,
+}
+
+/// The lines of `text` in `range` (all lines when `None`) that fit in
+/// `budget` tokens, always keeping at least the first line.
+fn excerpt(
+    text: &str,
+    range: Option<(usize, usize)>,
+    counter: &TokenCounter,
+    budget: usize,
+) -> Excerpt {
+    let lines: Vec<&str> = text.lines().collect();
+    let total = lines.len();
+    let (start, end) = range.unwrap_or((1, total.max(1)));
+    let from = start.min(total.max(1));
+    let end = end.min(total);
+    let mut out = String::new();
+    let mut tokens = 0;
+    let mut to = from.saturating_sub(1);
+    for (i, line) in lines.iter().enumerate().take(end).skip(from - 1) {
+        let line_tokens = counter.count(line) + 1;
+        if tokens + line_tokens > budget && i + 1 > from {
+            break;
+        }
+        out.push_str(line);
+        out.push('\n');
+        tokens += line_tokens;
+        to = i + 1;
+    }
+    Excerpt {
+        text: out,
+        from,
+        to,
+        total,
+        tokens,
+    }
+}
+
+/// How much of a pin made it into the prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinStatus {
+    /// The whole fact or file.
+    Full,
+    /// Lines `from..=to` of a `total`-line file, limited by the pinned range
+    /// or the token budget.
+    Partial {
+        from: usize,
+        to: usize,
+        total: usize,
+    },
+    /// The pinned file no longer exists.
+    Missing,
+}
+
+/// One pin as rendered into the prompt.
+#[derive(Debug, Clone)]
+pub struct RenderedPin {
+    pub label: String,
+    pub tokens: usize,
+    pub status: PinStatus,
+    pub by_agent: bool,
+}
+
+impl RenderedPin {
+    /// Suffix for listings: the included range of a partial file, or a
+    /// warning for a missing one.
+    pub fn status_note(&self) -> String {
+        match self.status {
+            PinStatus::Full => String::new(),
+            PinStatus::Partial { from, to, total } => {
+                format!(" (lines {}-{} of {})", from, to, total)
+            }
+            PinStatus::Missing => " (missing: file deleted, /unpin to drop)".to_string(),
+        }
+    }
+}
+
+/// Pins rendered for one prompt.
+#[derive(Debug, Clone, Default)]
+pub struct PinnedContext {
+    /// System prompt section; empty when nothing is pinned.
+    pub prompt: String,
+    pub pins: Vec<RenderedPin>,
+    /// Pinned files included in full.
+    full_files: Vec<PathBuf>,
+}
+
+impl PinnedContext {
+    /// Tokens the pins add to every request.
+    pub fn total_tokens(&self) -> usize {
+        self.pins.iter().map(|p| p.tokens).sum()
+    }
+
+    /// Pins whose file no longer exists.
+    pub fn missing(&self) -> impl Iterator<Item = &RenderedPin> {
+        self.pins.iter().filter(|p| p.status == PinStatus::Missing)
+    }
+
+    /// Replace `file_read` results for files that are pinned in full with a
+    /// pointer to the pinned copy, so the same content isn't sent twice.
+    /// Returns the number of results replaced.
+    pub fn collapse_duplicates(&self, messages: &mut [Message], workspace: Option<&Path>) -> usize {
+        if self.full_files.is_empty() {
+            return 0;
+        }
+        let pinned: Vec<PathBuf> = self
+            .full_files
+            .iter()
+            .filter_map(|p| p.canonicalize().ok())
+            .collect();
+        let mut reads: HashMap<String, String> = HashMap::new();
+        for message in messages.iter() {
+            for part in parts(&message.content) {
+                if let Content::ToolCall {
+                    id,
+                    name,
+                    arguments,
+                } = part
+                    && name == "file_read"
+                    && let Some(path) = arguments.get("path").and_then(|p| p.as_str())
+                    && resolve(workspace, Path::new(path))
+                        .canonicalize()
+                        .is_ok_and(|p| pinned.contains(&p))
+                {
+                    reads.insert(id.clone(), path.to_string());
+                }
+            }
+        }
+        if reads.is_empty() {
+            return 0;
+        }
+        let mut replaced = 0;
+        for message in messages.iter_mut() {
+            for part in parts_mut(&mut message.content) {
+                if let Content::ToolResult {
+                    call_id,
+                    output,
+                    is_error: false,
+                } = part
+                    && let Some(path) = reads.get(call_id.as_str())
+                {
+                    *output = format!(
+                        "[Contents of {} omitted: the file is pinned, see Pinned Context]",
+                        path
+                    );
+                    replaced += 1;
+                }
+            }
+        }
+        replaced
+    }
+}
+
+fn parts(content: &Content) -> Vec<&Content> {
+    match content {
+        Content::MultiPart { parts } => parts.iter().collect(),
+        other => vec![other],
+    }
+}
+
+fn parts_mut(content: &mut Content) -> Vec<&mut Content> {
+    match content {
+        Content::MultiPart { parts } => parts.iter_mut().collect(),
+        other => vec![other],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Role;
+
+    fn counter() -> TokenCounter {
+        TokenCounter::for_model("gpt-4o")
+    }
+
+    fn result_output(message: &Message) -> &str {
+        match &message.content {
+            Content::ToolResult { output, .. } => output,
+            other => panic!("expected a tool result, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_file_range_and_fact() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        std::fs::write(dir.path().join("docs/api.md"), "# API\n").unwrap();
+
+        assert_eq!(
+            PinTarget::parse("docs/api.md", dir.path()).unwrap(),
+            PinTarget::File {
+                path: "docs/api.md".into(),
+                lines: None
+            }
+        );
+        assert_eq!(
+            PinTarget::parse("docs/api.md:10-40", dir.path()).unwrap(),
+            PinTarget::File {
+                path: "docs/api.md".into(),
+                lines: Some((10, 40))
+            }
+        );
+        assert_eq!(
+            PinTarget::parse("the API contract is frozen", dir.path()).unwrap(),
+            PinTarget::Fact {
+                text: "the API contract is frozen".into()
+            }
+        );
+        assert!(PinTarget::parse("docs/missing.md", dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_pin_unpin_by_path_and_fact_prefix() {
+        let mut pins = PinSet::default();
+        assert!(pins.pin(
+            PinTarget::File {
+                path: "docs/api.md".into(),
+                lines: None
+            },
+            false
+        ));
+        assert!(!pins.pin(
+            PinTarget::File {
+                path: "docs/api.md".into(),
+                lines: None
+            },
+            true
+        ));
+        pins.pin(
+            PinTarget::Fact {
+                text: "Use snake_case for JSON".into(),
+            },
+            false,
+        );
+        pins.pin(
+            PinTarget::Fact {
+                text: "Use tabs in Makefiles".into(),
+            },
+            false,
+        );
+        assert_eq!(pins.len(), 3);
+
+        assert!(pins.unpin("use").is_none(), "ambiguous prefix");
+        assert_eq!(
+            pins.unpin("use snake").unwrap().label(),
+            "Use snake_case for JSON"
+        );
+        assert_eq!(pins.unpin("docs/api.md").unwrap().label(), "docs/api.md");
+        assert_eq!(pins.len(), 1);
+    }
+
+    #[test]
+    fn test_render_reads_current_contents_and_flags_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "first version\n").unwrap();
+        let mut pins = PinSet::default();
+        pins.pin(
+            PinTarget::File {
+                path: "a.txt".into(),
+                lines: None,
+            },
+            false,
+        );
+        pins.pin(
+            PinTarget::File {
+                path: "gone.txt".into(),
+                lines: None,
+            },
+            false,
+        );
+        pins.pin(
+            PinTarget::Fact {
+                text: "The API contract is in docs/api.md".into(),
+            },
+            false,
+        );
+
+        std::fs::write(dir.path().join("a.txt"), "second version\n").unwrap();
+        let rendered = pins.render(Some(dir.path()), &counter(), 1000);
+        assert!(rendered.prompt.contains("second version"));
+        assert!(!rendered.prompt.contains("first version"));
+        assert!(
+            rendered
+                .prompt
+                .contains("- The API contract is in docs/api.md")
+        );
+        assert!(rendered.prompt.contains("### gone.txt\n(missing"));
+        let missing: Vec<&str> = rendered.missing().map(|p| p.label.as_str()).collect();
+        assert_eq!(missing, vec!["gone.txt"]);
+        assert!(rendered.total_tokens() > 0);
+    }
+
+    #[test]
+    fn test_render_respects_range_and_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let body: String = (1..=200).map(|i| format!("line number {}\n", i)).collect();
+        std::fs::write(dir.path().join("big.rs"), body).unwrap();
+
+        let mut ranged = PinSet::default();
+        ranged.pin(
+            PinTarget::File {
+                path: "big.rs".into(),
+                lines: Some((10, 12)),
+            },
+            false,
+        );
+        let rendered = ranged.render(Some(dir.path()), &counter(), 1000);
+        assert_eq!(
+            rendered.pins[0].status,
+            PinStatus::Partial {
+                from: 10,
+                to: 12,
+                total: 200
+            }
+        );
+        assert!(
+            rendered
+                .prompt
+                .contains("line number 10\nline number 11\nline number 12\n")
+        );
+        assert!(!rendered.prompt.contains("line number 13\n"));
+
+        let mut whole = PinSet::default();
+        whole.pin(
+            PinTarget::File {
+                path: "big.rs".into(),
+                lines: None,
+            },
+            false,
+        );
+        let rendered = whole.render(Some(dir.path()), &counter(), 50);
+        match rendered.pins[0].status {
+            PinStatus::Partial { from, to, total } => {
+                assert_eq!((from, total), (1, 200));
+                assert!(to > 1 && to < 200);
+            }
+            other => panic!("expected a partial pin, got {:?}", other),
+        }
+        assert!(rendered.pins[0].tokens <= 50);
+        assert!(rendered.prompt.contains("pin `big.rs:<start>-<end>`"));
+    }
+
+    #[test]
+    fn test_collapse_duplicate_reads_of_pinned_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("api.md"), "contract\n").unwrap();
+        let mut pins = PinSet::default();
+        pins.pin(
+            PinTarget::File {
+                path: "api.md".into(),
+                lines: None,
+            },
+            false,
+        );
+        let rendered = pins.render(Some(dir.path()), &counter(), 1000);
+
+        let mut messages = vec![
+            Message::new(
+                Role::Assistant,
+                Content::tool_call("c1", "file_read", serde_json::json!({"path": "api.md"})),
+            ),
+            Message::tool_result("c1", "contract", false),
+            Message::new(
+                Role::Assistant,
+                Content::tool_call("c2", "file_read", serde_json::json!({"path": "other.md"})),
+            ),
+            Message::tool_result("c2", "other", false),
+        ];
+        assert_eq!(
+            rendered.collapse_duplicates(&mut messages, Some(dir.path())),
+            1
+        );
+        assert!(result_output(&messages[1]).contains("pinned"));
+        assert_eq!(result_output(&messages[3]), "other");
+    }
+
+    #[test]
+    fn test_pin_set_round_trips() {
+        let mut pins = PinSet::default();
+        pins.pin(
+            PinTarget::File {
+                path: "src/lib.rs".into(),
+                lines: Some((1, 20)),
+            },
+            true,
+        );
+        pins.pin(
+            PinTarget::Fact {
+                text: "fact".into(),
+            },
+            false,
+        );
+        let json = serde_json::to_string(&pins).unwrap();
+        let restored: PinSet = serde_json::from_str(&json).unwrap();
+        let labels: Vec<String> = restored.iter().map(|p| p.label()).collect();
+        assert_eq!(labels, vec!["src/lib.rs:1-20", "fact"]);
+        assert!(restored.iter().next().unwrap().by_agent);
+    }
+}
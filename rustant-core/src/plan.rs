@@ -8,6 +8,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::subtasks::SubTask;
 use crate::types::RiskLevel;
 
 /// Status of the overall execution plan.
@@ -96,6 +97,10 @@ pub struct PlanStep {
     /// Whether this step needs explicit user approval before execution.
     #[serde(default)]
     pub requires_approval: bool,
+    /// Shell command that succeeds once the step's effect is in place. Used
+    /// on resume to tell whether an interrupted step actually finished.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify: Option<String>,
 }
 
 impl Default for PlanStep {
@@ -110,6 +115,7 @@ impl Default for PlanStep {
             result: None,
             risk_level: None,
             requires_approval: false,
+            verify: None,
         }
     }
 }
@@ -157,6 +163,9 @@ pub struct ExecutionPlan {
     /// Whether this plan was generated via the LLM council.
     #[serde(default)]
     pub council_generated: bool,
+    /// Independent sub-tasks run concurrently before the steps (decomposition mode).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<SubTask>,
 }
 
 impl ExecutionPlan {
@@ -176,6 +185,7 @@ impl ExecutionPlan {
             current_step: None,
             estimated_cost: None,
             council_generated: false,
+            subtasks: Vec::new(),
         }
     }
 
@@ -292,6 +302,19 @@ impl std::fmt::Display for ExecutionPlan {
             )?;
         }
 
+        if !self.subtasks.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Parallel sub-tasks (run before the steps):")?;
+            for (i, subtask) in self.subtasks.iter().enumerate() {
+                let tools = if subtask.tools.is_empty() {
+                    String::new()
+                } else {
+                    format!(" [{}]", subtask.tools.join(", "))
+                };
+                writeln!(f, "  ◇ {}. {}{}", i + 1, subtask.description, tools)?;
+            }
+        }
+
         if !self.alternatives.is_empty() {
             writeln!(f)?;
             writeln!(f, "Alternatives considered:")?;
@@ -341,7 +364,8 @@ The JSON must have this structure:
       "tool_args": { ... } or null if tool arguments should be determined at execution time,
       "depends_on": [0, 1] (indices of prerequisite steps, empty array if none),
       "risk_level": "read_only" | "write" | "execute" | "network" | "destructive" | null,
-      "requires_approval": false
+      "requires_approval": false,
+      "verify": "shell command that succeeds once the step is done, or null"
     }
   ],
   "alternatives": [
@@ -361,6 +385,7 @@ Guidelines:
 - Maximum 20 steps
 - Set requires_approval=true for destructive or irreversible operations
 - Include tool_args only when you are confident about the values
+- Give verify for steps that change files or state, e.g. "test -f src/auth/mod.rs" or "cargo check"
 - Use depends_on to express execution order dependencies
 - List alternatives only when meaningfully different approaches exist
 - Include clarifications only for genuinely ambiguous requirements
@@ -468,6 +493,8 @@ fn build_plan_from_json(value: serde_json::Value, goal: &str) -> ExecutionPlan {
 
             let requires_approval = step_val["requires_approval"].as_bool().unwrap_or(false);
 
+            let verify = step_val["verify"].as_str().map(|s| s.to_string());
+
             steps.push(PlanStep {
                 index: i,
                 description: desc,
@@ -478,6 +505,7 @@ fn build_plan_from_json(value: serde_json::Value, goal: &str) -> ExecutionPlan {
                 result: None,
                 risk_level,
                 requires_approval,
+                verify,
             });
         }
     }
@@ -525,6 +553,7 @@ fn build_plan_from_json(value: serde_json::Value, goal: &str) -> ExecutionPlan {
         current_step: None,
         estimated_cost,
         council_generated: false,
+        subtasks: crate::subtasks::parse_subtasks(&value["subtasks"]),
     }
 }
 
@@ -563,6 +592,7 @@ fn fallback_single_step_plan(goal: &str, raw_text: &str) -> ExecutionPlan {
             result: None,
             risk_level: None,
             requires_approval: false,
+            verify: None,
         }],
         alternatives: Vec::new(),
         clarifications: Vec::new(),
@@ -572,6 +602,7 @@ fn fallback_single_step_plan(goal: &str, raw_text: &str) -> ExecutionPlan {
         current_step: None,
         estimated_cost: None,
         council_generated: false,
+        subtasks: Vec::new(),
     }
 }
 
@@ -586,6 +617,38 @@ pub struct PlanConfig {
     pub max_steps: usize,
     /// Whether to auto-approve read-only steps.
     pub auto_approve_readonly: bool,
+    /// Let the planner split work into independent sub-tasks run concurrently.
+    #[serde(default)]
+    pub decomposition: bool,
+    /// Maximum sub-tasks running at once; also capped by `llm.rate_limits.max_concurrent`.
+    #[serde(default = "default_max_parallel_subtasks")]
+    pub max_parallel_subtasks: usize,
+    /// Tool-call budget per sub-task, and the cap on budgets the planner asks for.
+    #[serde(default = "default_subtask_tool_budget")]
+    pub subtask_tool_budget: usize,
+    /// Estimated plan cost (USD) above which the user must confirm, adjust,
+    /// or cancel before execution. 0 asks for every plan.
+    #[serde(default = "default_cost_confirm_threshold")]
+    pub cost_confirm_threshold_usd: f64,
+    /// Unattended runs are held to the estimate times this margin.
+    #[serde(default = "default_cost_budget_margin")]
+    pub cost_budget_margin: f64,
+}
+
+fn default_cost_confirm_threshold() -> f64 {
+    0.50
+}
+
+fn default_cost_budget_margin() -> f64 {
+    1.5
+}
+
+fn default_max_parallel_subtasks() -> usize {
+    4
+}
+
+fn default_subtask_tool_budget() -> usize {
+    8
 }
 
 impl Default for PlanConfig {
@@ -595,6 +658,11 @@ impl Default for PlanConfig {
             use_council: false,
             max_steps: 20,
             auto_approve_readonly: false,
+            decomposition: false,
+            max_parallel_subtasks: default_max_parallel_subtasks(),
+            subtask_tool_budget: default_subtask_tool_budget(),
+            cost_confirm_threshold_usd: default_cost_confirm_threshold(),
+            cost_budget_margin: default_cost_budget_margin(),
         }
     }
 }
@@ -829,7 +897,8 @@ mod tests {
                     "tool_args": null,
                     "depends_on": [0],
                     "risk_level": null,
-                    "requires_approval": false
+                    "requires_approval": false,
+                    "verify": "test -s notes/structure.md"
                 }
             ],
             "alternatives": [
@@ -852,6 +921,11 @@ mod tests {
         assert_eq!(plan.steps[0].risk_level, Some(RiskLevel::ReadOnly));
         assert_eq!(plan.steps[1].depends_on, vec![0]);
         assert!(plan.steps[1].tool.is_none());
+        assert!(plan.steps[0].verify.is_none());
+        assert_eq!(
+            plan.steps[1].verify.as_deref(),
+            Some("test -s notes/structure.md")
+        );
         assert_eq!(plan.alternatives.len(), 1);
         assert_eq!(plan.estimated_cost, Some(0.02));
         assert_eq!(plan.status, PlanStatus::PendingReview);
@@ -1006,6 +1080,7 @@ mod tests {
             use_council: true,
             max_steps: 10,
             auto_approve_readonly: true,
+            ..PlanConfig::default()
         };
         let json = serde_json::to_string(&config).unwrap();
         let deserialized: PlanConfig = serde_json::from_str(&json).unwrap();
@@ -1041,6 +1116,33 @@ mod tests {
         let plan = parse_plan_json(json, "Goal");
         assert_eq!(plan.summary, "Do it");
         assert!(plan.steps.is_empty());
+        assert!(plan.subtasks.is_empty());
+    }
+
+    #[test]
+    fn test_parse_plan_json_with_subtasks() {
+        let json = r#"{
+            "summary": "Update headers per crate",
+            "subtasks": [
+                {"description": "Update header in crate a", "tools": ["file_read", "file_patch"], "paths": ["a/src/lib.rs"]},
+                {"description": "Update header in crate b", "tools": ["file_read", "file_patch"], "paths": ["b/src/lib.rs"], "max_tool_calls": 4}
+            ],
+            "steps": [{"description": "Verify the build", "tool": "shell_exec"}]
+        }"#;
+        let plan = parse_plan_json(json, "Update headers");
+        assert_eq!(plan.subtasks.len(), 2);
+        assert_eq!(plan.subtasks[1].max_tool_calls, Some(4));
+        assert_eq!(plan.steps.len(), 1);
+        assert!(plan.to_string().contains("Parallel sub-tasks"));
+
+        // Configs written before decomposition existed still load.
+        let config: PlanConfig = serde_json::from_str(
+            r#"{"enabled": true, "use_council": false, "max_steps": 5, "auto_approve_readonly": false}"#,
+        )
+        .unwrap();
+        assert!(!config.decomposition);
+        assert_eq!(config.max_parallel_subtasks, 4);
+        assert_eq!(config.subtask_tool_budget, 8);
     }
 
     #[test]
@@ -3,8 +3,35 @@
 //! Scans a workspace directory to identify the project's language, framework,
 //! and build system. Used by `rustant init` to generate optimal default
 //! configurations without requiring manual setup.
+//!
+//! Monorepos are scanned for nested subprojects (see [`detect_subprojects`]),
+//! each with its own manifest and commands, so project-specific commands can
+//! be routed to the right directory (see [`route_to_subproject`]).
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Directories never searched for subprojects: dependencies, build output,
+/// virtualenvs and tool state.
+const IGNORED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "vendor",
+    "third_party",
+    "dist",
+    "build",
+    "out",
+    "bin",
+    "obj",
+    "venv",
+    "env",
+    "__pycache__",
+    "site-packages",
+    "Pods",
+];
 
-use std::path::Path;
+/// How deep below the workspace root subprojects are looked for.
+const MAX_SUBPROJECT_DEPTH: usize = 4;
 
 /// Detected project type based on workspace analysis.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -43,6 +70,81 @@ impl std::fmt::Display for ProjectType {
     }
 }
 
+impl ProjectType {
+    /// Lowercase key used in config files, e.g. `rust` or `rust+node`.
+    pub fn key(&self) -> String {
+        match self {
+            ProjectType::Rust => "rust".into(),
+            ProjectType::Node => "node".into(),
+            ProjectType::Python => "python".into(),
+            ProjectType::Go => "go".into(),
+            ProjectType::Java => "java".into(),
+            ProjectType::Ruby => "ruby".into(),
+            ProjectType::CSharp => "csharp".into(),
+            ProjectType::Cpp => "cpp".into(),
+            ProjectType::Mixed(types) => {
+                types.iter().map(|t| t.key()).collect::<Vec<_>>().join("+")
+            }
+            ProjectType::Unknown => "unknown".into(),
+        }
+    }
+
+    /// Parse a [`key`](Self::key).
+    pub fn from_key(key: &str) -> Option<Self> {
+        if key.contains('+') {
+            let types = key
+                .split('+')
+                .map(|k| Self::from_key(k.trim()))
+                .collect::<Option<Vec<_>>>()?;
+            return Some(ProjectType::Mixed(types));
+        }
+        Some(match key.trim().to_ascii_lowercase().as_str() {
+            "rust" => ProjectType::Rust,
+            "node" | "nodejs" | "javascript" | "typescript" => ProjectType::Node,
+            "python" => ProjectType::Python,
+            "go" => ProjectType::Go,
+            "java" => ProjectType::Java,
+            "ruby" => ProjectType::Ruby,
+            "csharp" | "c#" => ProjectType::CSharp,
+            "cpp" | "c" | "c++" => ProjectType::Cpp,
+            "unknown" => ProjectType::Unknown,
+            _ => return None,
+        })
+    }
+
+    /// The individual languages, flattening `Mixed`.
+    pub fn languages(&self) -> Vec<ProjectType> {
+        match self {
+            ProjectType::Mixed(types) => types.iter().flat_map(|t| t.languages()).collect(),
+            ProjectType::Unknown => Vec::new(),
+            other => vec![other.clone()],
+        }
+    }
+
+    fn from_languages(mut types: Vec<ProjectType>) -> Self {
+        types.dedup();
+        match types.len() {
+            0 => ProjectType::Unknown,
+            1 => types.remove(0),
+            _ => ProjectType::Mixed(types),
+        }
+    }
+}
+
+impl Serialize for ProjectType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.key())
+    }
+}
+
+impl<'de> Deserialize<'de> for ProjectType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        ProjectType::from_key(&key)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown project type '{}'", key)))
+    }
+}
+
 /// Result of project detection with rich metadata.
 #[derive(Debug, Clone)]
 pub struct ProjectInfo {
@@ -64,10 +166,372 @@ pub struct ProjectInfo {
     pub has_ci: bool,
     /// Detected framework (e.g., "React", "Django", "Actix").
     pub framework: Option<String>,
+    /// Projects found in the workspace, including the root when it has a
+    /// manifest. More than one means a monorepo.
+    pub subprojects: Vec<Subproject>,
+}
+
+impl ProjectInfo {
+    /// Whether the workspace holds more than one project.
+    pub fn is_monorepo(&self) -> bool {
+        self.subprojects.len() > 1
+    }
+}
+
+/// A project inside the workspace, with its own manifest and commands.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subproject {
+    /// Short name used to refer to it, e.g. `frontend`.
+    pub name: String,
+    /// Directory relative to the workspace; `.` for the root.
+    pub path: PathBuf,
+    #[serde(rename = "type")]
+    pub project_type: ProjectType,
+    /// Manifest file that identified it, e.g. `package.json`.
+    pub manifest: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub framework: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package_manager: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub build_commands: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub test_commands: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lint_commands: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dev_commands: Vec<String>,
+}
+
+impl Subproject {
+    fn from_info(workspace: &Path, dir: &Path, info: &ProjectInfo) -> Self {
+        let path = match dir.strip_prefix(workspace) {
+            Ok(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let name = if path == Path::new(".") {
+            "root".to_string()
+        } else {
+            path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string())
+        };
+        let languages = info.project_type.languages();
+        let pm = info.package_manager.as_deref();
+        Self {
+            name,
+            path,
+            manifest: languages
+                .iter()
+                .find_map(|t| manifest_for(dir, t))
+                .unwrap_or_default(),
+            framework: info.framework.clone(),
+            package_manager: info.package_manager.clone(),
+            build_commands: info.build_commands.clone(),
+            test_commands: info.test_commands.clone(),
+            lint_commands: languages
+                .iter()
+                .flat_map(|t| lint_commands(t, pm))
+                .collect(),
+            dev_commands: languages
+                .iter()
+                .flat_map(|t| dev_commands(t, pm, info.framework.as_deref()))
+                .collect(),
+            project_type: info.project_type.clone(),
+        }
+    }
+
+    /// Whether this is the workspace root.
+    pub fn is_root(&self) -> bool {
+        self.path == Path::new(".")
+    }
+
+    /// Whether a workspace-relative path lies inside this subproject.
+    pub fn contains(&self, path: &Path) -> bool {
+        let path = path.strip_prefix(".").unwrap_or(path);
+        self.is_root() || path.starts_with(&self.path)
+    }
+
+    /// Commands this subproject's toolchain provides.
+    pub fn allowed_commands(&self) -> Vec<String> {
+        let mut commands = Vec::new();
+        for t in self.project_type.languages() {
+            for command in toolchain_commands(&t, self.package_manager.as_deref()) {
+                if !commands.contains(&command) {
+                    commands.push(command);
+                }
+            }
+        }
+        commands
+    }
+
+    /// Whether `program` (a command's first word) belongs to this subproject.
+    pub fn runs(&self, program: &str) -> bool {
+        self.allowed_commands().iter().any(|c| c == program)
+            || self
+                .build_commands
+                .iter()
+                .chain(&self.test_commands)
+                .chain(&self.lint_commands)
+                .chain(&self.dev_commands)
+                .any(|c| c.split_whitespace().next() == Some(program))
+    }
 }
 
 /// Detect the project type and metadata from a workspace directory.
+///
+/// A workspace root without a manifest of its own takes its type from the
+/// subprojects found below it.
 pub fn detect_project(workspace: &Path) -> ProjectInfo {
+    let mut info = detect_directory(workspace);
+
+    // Git detection
+    info.has_git = workspace.join(".git").exists();
+    info.git_clean = if info.has_git {
+        std::process::Command::new("git")
+            .args(["status", "--porcelain"])
+            .current_dir(workspace)
+            .output()
+            .map(|o| o.stdout.is_empty())
+            .unwrap_or(false)
+    } else {
+        false
+    };
+
+    info.subprojects = detect_subprojects(workspace);
+    if info.project_type == ProjectType::Unknown {
+        info.project_type = ProjectType::from_languages(
+            info.subprojects
+                .iter()
+                .flat_map(|s| s.project_type.languages())
+                .fold(Vec::new(), |mut acc, t| {
+                    if !acc.contains(&t) {
+                        acc.push(t);
+                    }
+                    acc
+                }),
+        );
+    }
+    info
+}
+
+/// Find the projects in a workspace: the root if it has a manifest, plus
+/// every nested directory with a manifest for a language not already covered
+/// by an enclosing project. A Rust workspace's member crates therefore stay
+/// part of the root, while a `frontend/package.json` below it is its own
+/// subproject. Dependency, build-output and hidden directories are skipped.
+pub fn detect_subprojects(workspace: &Path) -> Vec<Subproject> {
+    let mut found: Vec<Subproject> = Vec::new();
+    scan_for_subprojects(workspace, workspace, 0, &[], &mut found);
+
+    // Names must be unique; fall back to the full path for clashes.
+    let names: Vec<String> = found.iter().map(|s| s.name.clone()).collect();
+    for sub in &mut found {
+        if names.iter().filter(|n| **n == sub.name).count() > 1 {
+            sub.name = sub.path.to_string_lossy().replace('\\', "/");
+        }
+    }
+    found
+}
+
+fn scan_for_subprojects(
+    workspace: &Path,
+    dir: &Path,
+    depth: usize,
+    enclosing: &[ProjectType],
+    found: &mut Vec<Subproject>,
+) {
+    let info = detect_directory(dir);
+    let mut enclosing = enclosing.to_vec();
+    let languages = info.project_type.languages();
+    if languages.iter().any(|t| !enclosing.contains(t)) {
+        found.push(Subproject::from_info(workspace, dir, &info));
+        enclosing.extend(languages);
+    }
+    if depth >= MAX_SUBPROJECT_DEPTH {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut children: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|e| {
+            let name = e.file_name();
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && !IGNORED_DIRS.contains(&name.as_ref())
+        })
+        .map(|e| e.path())
+        .collect();
+    children.sort();
+    for child in children {
+        scan_for_subprojects(workspace, &child, depth + 1, &enclosing, found);
+    }
+}
+
+/// The subproject an operation belongs to.
+///
+/// An `explicit` name or path wins, and an unknown one is an error listing
+/// the known subprojects. Otherwise the subproject holding all of `paths`
+/// (workspace-relative files the task has touched) is chosen if its
+/// toolchain runs `command`; failing that, the only non-root subproject that
+/// runs it, provided the root does not. `None` leaves the command where it is.
+pub fn route_to_subproject<'a>(
+    subprojects: &'a [Subproject],
+    explicit: Option<&str>,
+    command: &str,
+    paths: &[PathBuf],
+) -> Result<Option<&'a Subproject>, String> {
+    if let Some(wanted) = explicit {
+        let wanted_path = Path::new(wanted.trim_end_matches('/'));
+        let wanted_path = wanted_path.strip_prefix(".").unwrap_or(wanted_path);
+        return subprojects
+            .iter()
+            .find(|s| {
+                s.name == wanted
+                    || s.path == wanted_path
+                    || (s.is_root() && wanted_path.as_os_str().is_empty())
+            })
+            .map(Some)
+            .ok_or_else(|| {
+                format!(
+                    "unknown subproject '{}'. Known subprojects: {}",
+                    wanted,
+                    subprojects
+                        .iter()
+                        .map(|s| format!("{} ({})", s.name, s.path.display()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            });
+    }
+
+    let program = command
+        .split_whitespace()
+        .find(|word| !word.contains('='))
+        .unwrap_or("");
+    if program.is_empty() {
+        return Ok(None);
+    }
+
+    let deepest = |path: &PathBuf| {
+        subprojects
+            .iter()
+            .filter(|s| !s.is_root() && s.contains(path))
+            .max_by_key(|s| s.path.components().count())
+    };
+    let mut by_path = paths.iter().map(deepest);
+    if let Some(Some(first)) = by_path.next()
+        && by_path.all(|s| s.is_some_and(|s| s.path == first.path))
+        && first.runs(program)
+    {
+        return Ok(Some(first));
+    }
+
+    if subprojects.iter().any(|s| s.is_root() && s.runs(program)) {
+        return Ok(None);
+    }
+    let mut runners = subprojects
+        .iter()
+        .filter(|s| !s.is_root() && s.runs(program));
+    match (runners.next(), runners.next()) {
+        (Some(only), None) => Ok(Some(only)),
+        _ => Ok(None),
+    }
+}
+
+/// System prompt note describing a monorepo's subprojects, or `None` for a
+/// single project.
+pub fn subproject_prompt(subprojects: &[Subproject]) -> Option<String> {
+    if subprojects.len() < 2 {
+        return None;
+    }
+    let mut out = String::from(
+        "This workspace is a monorepo. Project commands (build, test, lint, dev server) \
+         must run in the right subproject: pass `subproject` to shell_exec.\nSubprojects:\n",
+    );
+    for sub in subprojects {
+        out.push_str(&format!(
+            "- {} ({}, {}): {}\n",
+            sub.name,
+            sub.project_type,
+            sub.path.display(),
+            sub.test_commands
+                .iter()
+                .chain(&sub.lint_commands)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    Some(out)
+}
+
+/// Manifest file identifying `project_type` in `dir`.
+fn manifest_for(dir: &Path, project_type: &ProjectType) -> Option<String> {
+    let candidates: &[&str] = match project_type {
+        ProjectType::Rust => &["Cargo.toml"],
+        ProjectType::Node => &["package.json"],
+        ProjectType::Python => &["pyproject.toml", "setup.py", "requirements.txt", "Pipfile"],
+        ProjectType::Go => &["go.mod"],
+        ProjectType::Java => &["pom.xml", "build.gradle", "build.gradle.kts"],
+        ProjectType::Ruby => &["Gemfile"],
+        ProjectType::Cpp => &["CMakeLists.txt", "Makefile"],
+        ProjectType::CSharp => {
+            return std::fs::read_dir(dir)
+                .ok()?
+                .filter_map(|e| e.ok())
+                .find_map(|e| {
+                    let path = e.path();
+                    path.extension()
+                        .is_some_and(|ext| ext == "csproj" || ext == "sln")
+                        .then(|| e.file_name().to_string_lossy().to_string())
+                });
+        }
+        ProjectType::Mixed(_) | ProjectType::Unknown => &[],
+    };
+    candidates
+        .iter()
+        .find(|m| dir.join(m).exists())
+        .map(|m| m.to_string())
+}
+
+fn lint_commands(project_type: &ProjectType, package_manager: Option<&str>) -> Vec<String> {
+    match project_type {
+        ProjectType::Rust => vec!["cargo clippy".into(), "cargo fmt --check".into()],
+        ProjectType::Node => vec![format!("{} run lint", package_manager.unwrap_or("npm"))],
+        ProjectType::Python => vec!["ruff check .".into()],
+        ProjectType::Go => vec!["go vet ./...".into()],
+        ProjectType::Ruby => vec!["bundle exec rubocop".into()],
+        ProjectType::CSharp => vec!["dotnet format --verify-no-changes".into()],
+        _ => Vec::new(),
+    }
+}
+
+fn dev_commands(
+    project_type: &ProjectType,
+    package_manager: Option<&str>,
+    framework: Option<&str>,
+) -> Vec<String> {
+    match (project_type, framework) {
+        (ProjectType::Rust, Some("Tauri")) => vec!["cargo tauri dev".into()],
+        (ProjectType::Rust, _) => vec!["cargo run".into()],
+        (ProjectType::Node, _) => vec![format!("{} run dev", package_manager.unwrap_or("npm"))],
+        (ProjectType::Python, Some("Django")) => vec!["python manage.py runserver".into()],
+        (ProjectType::Python, Some("FastAPI")) => vec!["uvicorn main:app --reload".into()],
+        (ProjectType::Python, Some("Flask")) => vec!["flask run".into()],
+        (ProjectType::Go, _) => vec!["go run .".into()],
+        (ProjectType::Ruby, Some("Rails")) => vec!["bin/rails server".into()],
+        (ProjectType::CSharp, _) => vec!["dotnet run".into()],
+        _ => Vec::new(),
+    }
+}
+
+/// Detect the project in one directory, without looking at git state or
+/// subdirectories.
+fn detect_directory(workspace: &Path) -> ProjectInfo {
     let mut types = Vec::new();
     let mut build_commands = Vec::new();
     let mut test_commands = Vec::new();
@@ -242,19 +706,6 @@ pub fn detect_project(workspace: &Path) -> ProjectInfo {
         }
     }
 
-    // Git detection
-    let has_git = workspace.join(".git").exists();
-    let git_clean = if has_git {
-        std::process::Command::new("git")
-            .args(["status", "--porcelain"])
-            .current_dir(workspace)
-            .output()
-            .map(|o| o.stdout.is_empty())
-            .unwrap_or(false)
-    } else {
-        false
-    };
-
     // CI detection
     let has_ci = workspace.join(".github").join("workflows").exists()
         || workspace.join(".gitlab-ci.yml").exists()
@@ -270,22 +721,69 @@ pub fn detect_project(workspace: &Path) -> ProjectInfo {
 
     ProjectInfo {
         project_type,
-        has_git,
-        git_clean,
+        has_git: false,
+        git_clean: false,
         build_commands,
         test_commands,
         package_manager,
         source_dirs,
         has_ci,
         framework,
+        subprojects: Vec::new(),
     }
 }
 
-/// Generate recommended safety allowed_commands based on project type.
+/// Generate recommended safety allowed_commands based on project type,
+/// including every subproject's toolchain in a monorepo.
 pub fn recommended_allowed_commands(info: &ProjectInfo) -> Vec<String> {
-    let mut commands = vec!["git".to_string(), "echo".to_string(), "cat".to_string()];
+    attributed_allowed_commands(info)
+        .into_iter()
+        .map(|(command, _)| command)
+        .collect()
+}
+
+/// Recommended allowed commands with the subprojects that need each one.
+/// Commands needed by any project (such as `git`) have no attribution.
+pub fn attributed_allowed_commands(info: &ProjectInfo) -> Vec<(String, Vec<String>)> {
+    let mut commands: Vec<(String, Vec<String>)> = ["git", "echo", "cat"]
+        .iter()
+        .map(|c| (c.to_string(), Vec::new()))
+        .collect();
+    let mut add = |command: String, source: Option<&str>| {
+        let index = match commands.iter().position(|(c, _)| *c == command) {
+            Some(i) => i,
+            None => {
+                commands.push((command, Vec::new()));
+                commands.len() - 1
+            }
+        };
+        if let Some(source) = source
+            && !commands[index].1.iter().any(|s| s == source)
+        {
+            commands[index].1.push(source.to_string());
+        }
+    };
 
-    match &info.project_type {
+    if info.is_monorepo() {
+        for sub in &info.subprojects {
+            for command in sub.allowed_commands() {
+                add(command, Some(&sub.name));
+            }
+        }
+    } else {
+        for t in info.project_type.languages() {
+            for command in toolchain_commands(&t, info.package_manager.as_deref()) {
+                add(command, None);
+            }
+        }
+    }
+    commands
+}
+
+/// Commands a language's toolchain needs.
+fn toolchain_commands(project_type: &ProjectType, package_manager: Option<&str>) -> Vec<String> {
+    let mut commands: Vec<String> = Vec::new();
+    match project_type {
         ProjectType::Rust => {
             commands.extend([
                 "cargo".to_string(),
@@ -295,8 +793,8 @@ pub fn recommended_allowed_commands(info: &ProjectInfo) -> Vec<String> {
         }
         ProjectType::Node => {
             commands.extend(["node".to_string(), "npx".to_string()]);
-            if let Some(pm) = &info.package_manager {
-                commands.push(pm.clone());
+            if let Some(pm) = package_manager {
+                commands.push(pm.to_string());
             }
         }
         ProjectType::Python => {
@@ -305,16 +803,16 @@ pub fn recommended_allowed_commands(info: &ProjectInfo) -> Vec<String> {
                 "python3".to_string(),
                 "pytest".to_string(),
             ]);
-            if let Some(pm) = &info.package_manager {
-                commands.push(pm.clone());
+            if let Some(pm) = package_manager {
+                commands.push(pm.to_string());
             }
         }
         ProjectType::Go => {
             commands.extend(["go".to_string(), "gofmt".to_string()]);
         }
         ProjectType::Java => {
-            if let Some(pm) = &info.package_manager {
-                match pm.as_str() {
+            if let Some(pm) = package_manager {
+                match pm {
                     "maven" => commands.push("mvn".to_string()),
                     "gradle" => commands.push("./gradlew".to_string()),
                     _ => {}
@@ -332,66 +830,74 @@ pub fn recommended_allowed_commands(info: &ProjectInfo) -> Vec<String> {
         }
         ProjectType::Mixed(types) => {
             for t in types {
-                let sub_info = ProjectInfo {
-                    project_type: t.clone(),
-                    has_git: false,
-                    git_clean: false,
-                    build_commands: vec![],
-                    test_commands: vec![],
-                    package_manager: info.package_manager.clone(),
-                    source_dirs: vec![],
-                    has_ci: false,
-                    framework: None,
-                };
-                commands.extend(recommended_allowed_commands(&sub_info));
+                for command in toolchain_commands(t, package_manager) {
+                    if !commands.contains(&command) {
+                        commands.push(command);
+                    }
+                }
             }
-            commands.sort();
-            commands.dedup();
         }
         ProjectType::Unknown => {}
     }
-
     commands
 }
 
-/// Generate example tasks tailored to the detected project type.
+/// Generate example tasks tailored to the detected project type. In a
+/// monorepo, each subproject contributes tasks prefixed with its name.
 pub fn example_tasks(info: &ProjectInfo) -> Vec<String> {
-    let mut tasks = Vec::new();
-
-    match &info.project_type {
-        ProjectType::Rust => {
-            tasks.push("\"Fix the compiler warnings in src/main.rs\"".to_string());
-            tasks.push("\"Add error handling to the database module\"".to_string());
-            tasks.push("\"Write tests for the authentication logic\"".to_string());
-        }
-        ProjectType::Node => {
-            tasks.push("\"Add input validation to the API endpoints\"".to_string());
-            tasks.push("\"Fix the failing test in auth.test.ts\"".to_string());
-            tasks.push("\"Refactor the user service to use async/await\"".to_string());
-        }
-        ProjectType::Python => {
-            tasks.push("\"Add type hints to the data processing module\"".to_string());
-            tasks.push("\"Write unit tests for the API handlers\"".to_string());
-            tasks.push("\"Fix the race condition in the worker pool\"".to_string());
-        }
-        ProjectType::Go => {
-            tasks.push("\"Add error wrapping to the HTTP handlers\"".to_string());
-            tasks.push("\"Write table-driven tests for the parser\"".to_string());
-            tasks.push("\"Implement graceful shutdown for the server\"".to_string());
-        }
-        ProjectType::Java => {
-            tasks.push("\"Add null safety checks to the service layer\"".to_string());
-            tasks.push("\"Write integration tests for the REST controllers\"".to_string());
-            tasks.push("\"Refactor the DAO layer to use the repository pattern\"".to_string());
-        }
-        _ => {
-            tasks.push("\"Find and fix bugs in the codebase\"".to_string());
-            tasks.push("\"Add tests for the main module\"".to_string());
-            tasks.push("\"Explain the architecture of this project\"".to_string());
-        }
+    if info.is_monorepo() {
+        return info
+            .subprojects
+            .iter()
+            .flat_map(|sub| {
+                let languages = sub.project_type.languages();
+                let primary = languages.first().cloned().unwrap_or(ProjectType::Unknown);
+                tasks_for(&primary)
+                    .into_iter()
+                    .take(2)
+                    .map(move |task| format!("\"[{}] {}\"", sub.name, task))
+            })
+            .collect();
     }
+    tasks_for(&info.project_type)
+        .into_iter()
+        .map(|task| format!("\"{}\"", task))
+        .collect()
+}
 
-    tasks
+fn tasks_for(project_type: &ProjectType) -> Vec<&'static str> {
+    match project_type {
+        ProjectType::Rust => vec![
+            "Fix the compiler warnings in src/main.rs",
+            "Add error handling to the database module",
+            "Write tests for the authentication logic",
+        ],
+        ProjectType::Node => vec![
+            "Add input validation to the API endpoints",
+            "Fix the failing test in auth.test.ts",
+            "Refactor the user service to use async/await",
+        ],
+        ProjectType::Python => vec![
+            "Add type hints to the data processing module",
+            "Write unit tests for the API handlers",
+            "Fix the race condition in the worker pool",
+        ],
+        ProjectType::Go => vec![
+            "Add error wrapping to the HTTP handlers",
+            "Write table-driven tests for the parser",
+            "Implement graceful shutdown for the server",
+        ],
+        ProjectType::Java => vec![
+            "Add null safety checks to the service layer",
+            "Write integration tests for the REST controllers",
+            "Refactor the DAO layer to use the repository pattern",
+        ],
+        _ => vec![
+            "Find and fix bugs in the codebase",
+            "Add tests for the main module",
+            "Explain the architecture of this project",
+        ],
+    }
 }
 
 #[cfg(test)]
@@ -527,6 +1033,7 @@ mod tests {
             source_dirs: vec!["src".to_string()],
             has_ci: false,
             framework: None,
+            subprojects: vec![],
         };
         let cmds = recommended_allowed_commands(&info);
         assert!(cmds.contains(&"cargo".to_string()));
@@ -545,6 +1052,7 @@ mod tests {
             source_dirs: vec![],
             has_ci: false,
             framework: None,
+            subprojects: vec![],
         };
         let tasks = example_tasks(&info);
         assert!(!tasks.is_empty());
@@ -563,6 +1071,110 @@ mod tests {
         assert_eq!(info.framework, Some("Axum".to_string()));
     }
 
+    fn monorepo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("backend/src")).unwrap();
+        std::fs::write(root.join("backend/Cargo.toml"), "[package]\nname = \"api\"").unwrap();
+        std::fs::create_dir_all(root.join("backend/crates/db")).unwrap();
+        std::fs::write(root.join("backend/crates/db/Cargo.toml"), "[package]").unwrap();
+        std::fs::create_dir_all(root.join("frontend/node_modules/left-pad")).unwrap();
+        std::fs::write(
+            root.join("frontend/package.json"),
+            r#"{"dependencies": {"next": "14"}}"#,
+        )
+        .unwrap();
+        std::fs::write(root.join("frontend/pnpm-lock.yaml"), "").unwrap();
+        std::fs::write(
+            root.join("frontend/node_modules/left-pad/package.json"),
+            "{}",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("pipeline")).unwrap();
+        std::fs::write(root.join("pipeline/pyproject.toml"), "[project]").unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::write(root.join("target/debug/go.mod"), "module x").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_detect_monorepo_subprojects() {
+        let dir = monorepo();
+        let info = detect_project(dir.path());
+        assert!(info.is_monorepo());
+        let names: Vec<&str> = info.subprojects.iter().map(|s| s.name.as_str()).collect();
+        // Member crates stay with their workspace; node_modules and target are skipped.
+        assert_eq!(names, ["backend", "frontend", "pipeline"]);
+        assert_eq!(
+            info.project_type,
+            ProjectType::Mixed(vec![
+                ProjectType::Rust,
+                ProjectType::Node,
+                ProjectType::Python
+            ])
+        );
+
+        let frontend = &info.subprojects[1];
+        assert_eq!(frontend.path, PathBuf::from("frontend"));
+        assert_eq!(frontend.manifest, "package.json");
+        assert_eq!(frontend.framework.as_deref(), Some("Next.js"));
+        assert_eq!(frontend.test_commands, ["pnpm test"]);
+        assert_eq!(frontend.lint_commands, ["pnpm run lint"]);
+
+        let attributed = attributed_allowed_commands(&info);
+        let cargo = attributed.iter().find(|(c, _)| c == "cargo").unwrap();
+        assert_eq!(cargo.1, ["backend"]);
+        assert!(attributed.iter().any(|(c, s)| c == "git" && s.is_empty()));
+        let tasks = example_tasks(&info);
+        assert!(tasks.iter().any(|t| t.starts_with("\"[pipeline] ")));
+    }
+
+    #[test]
+    fn test_route_to_subproject() {
+        let dir = monorepo();
+        let subs = detect_subprojects(dir.path());
+
+        let explicit = route_to_subproject(&subs, Some("frontend/"), "ls", &[]).unwrap();
+        assert_eq!(explicit.unwrap().name, "frontend");
+        assert!(
+            route_to_subproject(&subs, Some("mobile"), "ls", &[])
+                .unwrap_err()
+                .contains("Known subprojects: backend (backend)")
+        );
+
+        // Files touched by the task decide, if that subproject runs the command.
+        let touched = [PathBuf::from("pipeline/etl/load.py")];
+        let routed = route_to_subproject(&subs, None, "pytest -x", &touched).unwrap();
+        assert_eq!(routed.unwrap().name, "pipeline");
+        // Otherwise the only subproject whose toolchain runs it.
+        let routed = route_to_subproject(&subs, None, "cargo test", &touched).unwrap();
+        assert_eq!(routed.unwrap().name, "backend");
+        let routed = route_to_subproject(&subs, None, "RUST_LOG=debug cargo run", &[]).unwrap();
+        assert_eq!(routed.unwrap().name, "backend");
+        assert!(
+            route_to_subproject(&subs, None, "ls -la", &[])
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_subproject_config_round_trip() {
+        let dir = monorepo();
+        let subs = detect_subprojects(dir.path());
+        let json = serde_json::to_value(&subs[0]).unwrap();
+        assert_eq!(json["type"], "rust");
+        let back: Subproject = serde_json::from_value(json).unwrap();
+        assert_eq!(back, subs[0]);
+        assert_eq!(
+            ProjectType::from_key("rust+node"),
+            Some(ProjectType::Mixed(vec![
+                ProjectType::Rust,
+                ProjectType::Node
+            ]))
+        );
+    }
+
     #[test]
     fn test_project_type_display() {
         assert_eq!(ProjectType::Rust.to_string(), "Rust");
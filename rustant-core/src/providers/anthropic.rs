@@ -179,6 +179,7 @@ impl AnthropicProvider {
     /// - `Content::ToolCall` -> `{"type": "tool_use", "id": "...", "name": "...", "input": {...}}`
     /// - `Content::ToolResult` -> `{"type": "tool_result", "tool_use_id": "...", "content": "..."}`
     /// - `Content::MultiPart` -> array of the above blocks
+    /// - `Content::Attachment` -> `{"type": "image", ...}` once resolved, else its reference as text
     fn message_to_anthropic_json(msg: &Message) -> Value {
         let role = match msg.role {
             Role::User | Role::Tool => "user",
@@ -240,6 +241,24 @@ impl AnthropicProvider {
                     .collect();
                 Value::Array(blocks)
             }
+            Content::Attachment {
+                image: Some(image), ..
+            } => {
+                serde_json::json!([{
+                    "type": "image",
+                    "source": {
+                        "type": "base64",
+                        "media_type": image.media_type,
+                        "data": image.data,
+                    },
+                }])
+            }
+            Content::Attachment { handle, .. } => {
+                serde_json::json!([{
+                    "type": "text",
+                    "text": handle.reference(),
+                }])
+            }
         }
     }
 
@@ -392,6 +411,7 @@ impl AnthropicProvider {
             usage,
             model,
             finish_reason,
+            cache_hit: None,
         })
     }
 
@@ -743,6 +763,11 @@ impl LlmProvider for AnthropicProvider {
         true
     }
 
+    /// Claude 3 and later accept image content blocks.
+    fn supports_vision(&self) -> bool {
+        !self.model.starts_with("claude-2") && !self.model.starts_with("claude-instant")
+    }
+
     /// Return the cost per token (input, output) in USD.
     fn cost_per_token(&self) -> (f64, f64) {
         (self.cost_input, self.cost_output)
@@ -752,6 +777,31 @@ impl LlmProvider for AnthropicProvider {
     fn model_name(&self) -> &str {
         &self.model
     }
+
+    /// Count tokens with Anthropic's `messages/count_tokens` endpoint.
+    async fn count_tokens(&self, text: &str) -> Option<usize> {
+        let url = format!("{}/messages/count_tokens", self.base_url);
+        let body = serde_json::json!({
+            "model": self.model,
+            "messages": [{"role": "user", "content": text}],
+        });
+        let response = self
+            .client
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .header("content-type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            debug!(status = %response.status(), "Anthropic token counting unavailable");
+            return None;
+        }
+        let json: Value = response.json().await.ok()?;
+        json["input_tokens"].as_u64().map(|n| n as usize)
+    }
 }
 
 #[cfg(test)]
@@ -772,10 +822,12 @@ mod tests {
             output_cost_per_million: 15.0,
             use_streaming: false,
             fallback_providers: Vec::new(),
+            vision_fallback: None,
             credential_store_key: None,
             auth_method: String::new(),
             api_key: None,
             retry: crate::config::RetryConfig::default(),
+            rate_limits: crate::config::RateLimitConfig::default(),
         }
     }
 
@@ -223,6 +223,7 @@ impl LlmProvider for FailoverProvider {
                         error = %e,
                         "Provider failed, trying next"
                     );
+                    crate::metrics::record_failover(entry.provider.model_name());
                     let mut cb = entry.circuit_breaker.lock().await;
                     cb.record_failure();
                     last_error = Some(e);
@@ -266,6 +267,7 @@ impl LlmProvider for FailoverProvider {
                         error = %e,
                         "Provider streaming failed, trying next"
                     );
+                    crate::metrics::record_failover(entry.provider.model_name());
                     let mut cb = entry.circuit_breaker.lock().await;
                     cb.record_failure();
                     last_error = Some(e);
@@ -290,6 +292,11 @@ impl LlmProvider for FailoverProvider {
         self.primary().supports_tools()
     }
 
+    /// Only when every provider in the chain can take the resolved images.
+    fn supports_vision(&self) -> bool {
+        self.providers.iter().all(|e| e.provider.supports_vision())
+    }
+
     fn cost_per_token(&self) -> (f64, f64) {
         self.primary().cost_per_token()
     }
@@ -297,6 +304,10 @@ impl LlmProvider for FailoverProvider {
     fn model_name(&self) -> &str {
         self.primary().model_name()
     }
+
+    async fn count_tokens(&self, text: &str) -> Option<usize> {
+        self.primary().count_tokens(text).await
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -270,6 +270,19 @@ impl GeminiProvider {
                     .collect();
                 Value::Array(gemini_parts)
             }
+            Content::Attachment {
+                image: Some(image), ..
+            } => {
+                serde_json::json!([{
+                    "inlineData": {
+                        "mimeType": image.media_type,
+                        "data": image.data,
+                    }
+                }])
+            }
+            Content::Attachment { handle, .. } => {
+                serde_json::json!([{"text": handle.reference()}])
+            }
         }
     }
 
@@ -531,6 +544,7 @@ impl GeminiProvider {
             usage,
             model,
             finish_reason,
+            cache_hit: None,
         })
     }
 
@@ -855,6 +869,11 @@ impl LlmProvider for GeminiProvider {
         true
     }
 
+    /// Gemini models are multimodal and accept inline image data.
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
     /// Return the cost per token (input, output) in USD.
     fn cost_per_token(&self) -> (f64, f64) {
         (self.cost_input, self.cost_output)
@@ -884,10 +903,12 @@ mod tests {
             output_cost_per_million: 0.30,
             use_streaming: false,
             fallback_providers: Vec::new(),
+            vision_fallback: None,
             credential_store_key: None,
             auth_method: String::new(),
             api_key: None,
             retry: crate::config::RetryConfig::default(),
+            rate_limits: crate::config::RateLimitConfig::default(),
         }
     }
 
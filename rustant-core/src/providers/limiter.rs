@@ -0,0 +1,470 @@
+//! Per-provider request concurrency limiting shared across agents.
+//!
+//! Every provider created through [`create_provider`](super::create_provider)
+//! is wrapped in a [`LimitedProvider`] that acquires a slot from a process-wide
+//! [`ProviderLimiter`] before sending. Limiters are keyed by provider, endpoint,
+//! and credential source, so the main agent, council members, and spawned
+//! agents that share an API key also share its concurrency budget.
+//!
+//! Waiting is FIFO (tokio's semaphore is fair). A caller that cannot get a slot
+//! within `max_queue_wait_secs` receives [`LlmError::Saturated`], which is not
+//! retried. When a request comes back `429`, the limiter records a cooldown so
+//! queued callers hold off until `retry_after` has passed; the caller that got
+//! the `429` releases its slot and sleeps in the retry layer, so by the time it
+//! re-acquires the cooldown has expired and it does not wait twice.
+
+use crate::brain::LlmProvider;
+use crate::config::{LlmConfig, RateLimitConfig};
+use crate::error::LlmError;
+use crate::types::{CompletionRequest, CompletionResponse, Message, StreamEvent};
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc};
+
+/// Number of recent queue waits kept for percentile calculation.
+const WAIT_SAMPLES: usize = 512;
+
+/// Point-in-time statistics for one provider limiter.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LimiterStats {
+    /// Limiter key (`provider|endpoint|credential source`); never contains the secret.
+    pub key: String,
+    /// Configured maximum concurrent requests.
+    pub max_concurrent: usize,
+    /// Requests currently holding a slot.
+    pub in_flight: usize,
+    /// Requests waiting for a slot.
+    pub queued: usize,
+    /// 95th percentile queue wait over recent requests, in milliseconds.
+    pub wait_p95_ms: u64,
+    /// Requests rejected with `Saturated` since startup.
+    pub saturated_total: u64,
+}
+
+/// A FIFO concurrency limiter for one provider credential.
+pub struct ProviderLimiter {
+    key: String,
+    max_concurrent: AtomicUsize,
+    max_queue_wait: Mutex<Duration>,
+    semaphore: Arc<Semaphore>,
+    queued: AtomicUsize,
+    saturated_total: AtomicU64,
+    cooldown_until: Mutex<Option<Instant>>,
+    waits_ms: Mutex<VecDeque<u64>>,
+}
+
+impl std::fmt::Debug for ProviderLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProviderLimiter")
+            .field("key", &self.key)
+            .field(
+                "max_concurrent",
+                &self.max_concurrent.load(Ordering::SeqCst),
+            )
+            .finish()
+    }
+}
+
+impl ProviderLimiter {
+    /// Create a standalone limiter. Most callers should use [`limiter_for`].
+    pub fn new(key: impl Into<String>, max_concurrent: usize, max_queue_wait: Duration) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            key: key.into(),
+            max_concurrent: AtomicUsize::new(max_concurrent),
+            max_queue_wait: Mutex::new(max_queue_wait),
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            queued: AtomicUsize::new(0),
+            saturated_total: AtomicU64::new(0),
+            cooldown_until: Mutex::new(None),
+            waits_ms: Mutex::new(VecDeque::with_capacity(WAIT_SAMPLES)),
+        }
+    }
+
+    /// The limiter key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Wait for a request slot, honouring any active 429 cooldown.
+    ///
+    /// Returns [`LlmError::Saturated`] if no slot frees up within the
+    /// configured maximum queue wait.
+    pub async fn acquire(&self) -> Result<OwnedSemaphorePermit, LlmError> {
+        let start = Instant::now();
+        let max_queue_wait = *self.max_queue_wait.lock().unwrap();
+        self.queued.fetch_add(1, Ordering::SeqCst);
+        let result = tokio::time::timeout(max_queue_wait, async {
+            loop {
+                if let Some(remaining) = self.cooldown_remaining() {
+                    tokio::time::sleep(remaining).await;
+                }
+                let permit = Arc::clone(&self.semaphore)
+                    .acquire_owned()
+                    .await
+                    .expect("limiter semaphore is never closed");
+                // A 429 may have arrived while we were queued.
+                if self.cooldown_remaining().is_none() {
+                    return permit;
+                }
+            }
+        })
+        .await;
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+
+        let waited_ms = start.elapsed().as_millis() as u64;
+        match result {
+            Ok(permit) => {
+                let mut waits = self.waits_ms.lock().unwrap();
+                if waits.len() == WAIT_SAMPLES {
+                    waits.pop_front();
+                }
+                waits.push_back(waited_ms);
+                Ok(permit)
+            }
+            Err(_) => {
+                self.saturated_total.fetch_add(1, Ordering::SeqCst);
+                tracing::warn!(
+                    limiter = %self.key,
+                    waited_ms,
+                    "Provider saturated; request not sent"
+                );
+                Err(LlmError::Saturated {
+                    provider: self.key.clone(),
+                    waited_ms,
+                })
+            }
+        }
+    }
+
+    /// Record a `429` so queued requests hold off for `retry_after_secs`.
+    pub fn note_rate_limited(&self, retry_after_secs: u64) {
+        if retry_after_secs == 0 {
+            return;
+        }
+        let until = Instant::now() + Duration::from_secs(retry_after_secs);
+        let mut cooldown = self.cooldown_until.lock().unwrap();
+        if cooldown.is_none_or(|existing| existing < until) {
+            *cooldown = Some(until);
+        }
+    }
+
+    /// Change the limits of a live limiter.
+    ///
+    /// Raising `max_concurrent` releases slots immediately. Lowering it retires
+    /// idle slots now and the rest as in-flight requests finish, so no request
+    /// is interrupted. Must be called within a Tokio runtime.
+    pub fn set_limits(&self, max_concurrent: usize, max_queue_wait: Duration) {
+        *self.max_queue_wait.lock().unwrap() = max_queue_wait;
+        let max_concurrent = max_concurrent.max(1);
+        let previous = self.max_concurrent.swap(max_concurrent, Ordering::SeqCst);
+        if max_concurrent > previous {
+            self.semaphore.add_permits(max_concurrent - previous);
+        } else if max_concurrent < previous {
+            let excess = previous - max_concurrent;
+            let retiring = excess - self.semaphore.forget_permits(excess);
+            if retiring > 0 {
+                let semaphore = Arc::clone(&self.semaphore);
+                tokio::spawn(async move {
+                    if let Ok(permits) = semaphore.acquire_many_owned(retiring as u32).await {
+                        permits.forget();
+                    }
+                });
+            }
+        }
+    }
+
+    fn cooldown_remaining(&self) -> Option<Duration> {
+        let cooldown = *self.cooldown_until.lock().unwrap();
+        cooldown
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .filter(|d| !d.is_zero())
+    }
+
+    /// Current statistics.
+    pub fn stats(&self) -> LimiterStats {
+        let waits = self.waits_ms.lock().unwrap();
+        let wait_p95_ms = if waits.is_empty() {
+            0
+        } else {
+            let mut sorted: Vec<u64> = waits.iter().copied().collect();
+            sorted.sort_unstable();
+            let idx = ((sorted.len() as f64 * 0.95).ceil() as usize).saturating_sub(1);
+            sorted[idx.min(sorted.len() - 1)]
+        };
+        let max_concurrent = self.max_concurrent.load(Ordering::SeqCst);
+        LimiterStats {
+            key: self.key.clone(),
+            max_concurrent,
+            // Saturating: slots being retired after a limit decrease are
+            // counted as in flight until they are returned.
+            in_flight: max_concurrent.saturating_sub(self.semaphore.available_permits()),
+            queued: self.queued.load(Ordering::SeqCst),
+            wait_p95_ms,
+            saturated_total: self.saturated_total.load(Ordering::SeqCst),
+        }
+    }
+}
+
+fn registry() -> &'static Mutex<HashMap<String, Arc<ProviderLimiter>>> {
+    static LIMITERS: OnceLock<Mutex<HashMap<String, Arc<ProviderLimiter>>>> = OnceLock::new();
+    LIMITERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Build the limiter key for a provider config.
+///
+/// Uses the credential *source* (credential store key or env var name), never
+/// the secret value.
+pub fn limiter_key(config: &LlmConfig) -> String {
+    let credential = config
+        .credential_store_key
+        .as_deref()
+        .unwrap_or(&config.api_key_env);
+    format!(
+        "{}|{}|{}",
+        config.provider,
+        config.base_url.as_deref().unwrap_or("default"),
+        credential
+    )
+}
+
+/// Get (or create) the shared limiter for a provider config.
+///
+/// Returns `None` when limiting is disabled (`max_concurrent = 0`). The first
+/// config registered for a key sets its limits; [`reconfigure_limiter`]
+/// changes them later.
+pub fn limiter_for(config: &LlmConfig) -> Option<Arc<ProviderLimiter>> {
+    let RateLimitConfig {
+        max_concurrent,
+        max_queue_wait_secs,
+    } = config.rate_limits;
+    if max_concurrent == 0 {
+        return None;
+    }
+    let key = limiter_key(config);
+    let mut limiters = registry().lock().unwrap();
+    let limiter = limiters.entry(key.clone()).or_insert_with(|| {
+        Arc::new(ProviderLimiter::new(
+            key,
+            max_concurrent,
+            Duration::from_secs(max_queue_wait_secs),
+        ))
+    });
+    Some(Arc::clone(limiter))
+}
+
+/// Apply `config.rate_limits` to the limiter already registered for its key.
+///
+/// Returns `Ok(false)` when no limiter is registered yet (the new limits take
+/// effect on first use), and an error when asked to disable limiting, since
+/// providers created with a limiter keep it until restart.
+pub fn reconfigure_limiter(config: &LlmConfig) -> Result<bool, String> {
+    let RateLimitConfig {
+        max_concurrent,
+        max_queue_wait_secs,
+    } = config.rate_limits;
+    let key = limiter_key(config);
+    let limiters = registry().lock().unwrap();
+    let Some(limiter) = limiters.get(&key) else {
+        return Ok(false);
+    };
+    if max_concurrent == 0 {
+        return Err("disabling the provider limiter requires a restart".to_string());
+    }
+    limiter.set_limits(max_concurrent, Duration::from_secs(max_queue_wait_secs));
+    Ok(true)
+}
+
+/// Statistics for every registered limiter, sorted by key.
+pub fn limiter_stats() -> Vec<LimiterStats> {
+    let limiters = registry().lock().unwrap();
+    let mut stats: Vec<LimiterStats> = limiters.values().map(|l| l.stats()).collect();
+    stats.sort_by(|a, b| a.key.cmp(&b.key));
+    stats
+}
+
+/// An `LlmProvider` that acquires a limiter slot around every request.
+pub struct LimitedProvider {
+    inner: Arc<dyn LlmProvider>,
+    limiter: Arc<ProviderLimiter>,
+}
+
+impl LimitedProvider {
+    /// Wrap a provider with a shared limiter.
+    pub fn new(inner: Arc<dyn LlmProvider>, limiter: Arc<ProviderLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+
+    fn observe<T>(&self, result: &Result<T, LlmError>) {
+        if let Err(LlmError::RateLimited { retry_after_secs }) = result {
+            self.limiter.note_rate_limited(*retry_after_secs);
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for LimitedProvider {
+    async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, LlmError> {
+        let _permit = self.limiter.acquire().await?;
+        let result = self.inner.complete(request).await;
+        self.observe(&result);
+        result
+    }
+
+    async fn complete_streaming(
+        &self,
+        request: CompletionRequest,
+        tx: mpsc::Sender<StreamEvent>,
+    ) -> Result<(), LlmError> {
+        let _permit = self.limiter.acquire().await?;
+        let result = self.inner.complete_streaming(request, tx).await;
+        self.observe(&result);
+        result
+    }
+
+    fn estimate_tokens(&self, messages: &[Message]) -> usize {
+        self.inner.estimate_tokens(messages)
+    }
+
+    fn context_window(&self) -> usize {
+        self.inner.context_window()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.inner.supports_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    fn cost_per_token(&self) -> (f64, f64) {
+        self.inner.cost_per_token()
+    }
+
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    async fn count_tokens(&self, text: &str) -> Option<usize> {
+        self.inner.count_tokens(text).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brain::MockLlmProvider;
+
+    #[tokio::test]
+    async fn test_limiter_caps_in_flight_and_saturates() {
+        let limiter = ProviderLimiter::new("test|default|KEY", 1, Duration::from_millis(50));
+        let held = limiter.acquire().await.unwrap();
+        assert_eq!(limiter.stats().in_flight, 1);
+
+        let err = limiter.acquire().await.unwrap_err();
+        assert!(matches!(err, LlmError::Saturated { .. }));
+        assert!(LlmError::is_saturation_message(&err.to_string()));
+
+        drop(held);
+        let stats = limiter.stats();
+        assert_eq!(stats.in_flight, 0);
+        assert_eq!(stats.queued, 0);
+        assert_eq!(stats.saturated_total, 1);
+        assert!(limiter.acquire().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_limiter_fifo_release() {
+        let limiter = Arc::new(ProviderLimiter::new("fifo", 1, Duration::from_secs(5)));
+        let held = limiter.acquire().await.unwrap();
+
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move { limiter.acquire().await.map(|_| ()) })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(limiter.stats().queued, 1);
+
+        drop(held);
+        waiter.await.unwrap().unwrap();
+        assert!(limiter.stats().wait_p95_ms >= 10);
+    }
+
+    #[tokio::test]
+    async fn test_cooldown_holds_queued_requests() {
+        let limiter = ProviderLimiter::new("cooldown", 2, Duration::from_millis(100));
+        limiter.note_rate_limited(5);
+        // Cooldown longer than the queue wait: the request is not sent.
+        assert!(matches!(
+            limiter.acquire().await,
+            Err(LlmError::Saturated { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_limited_provider_shares_limiter_by_key() {
+        let config = LlmConfig {
+            provider: "limiter-test".into(),
+            rate_limits: RateLimitConfig {
+                max_concurrent: 2,
+                max_queue_wait_secs: 1,
+            },
+            ..Default::default()
+        };
+        let a = limiter_for(&config).unwrap();
+        let b = limiter_for(&config).unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(a.key(), "limiter-test|default|OPENAI_API_KEY");
+
+        let disabled = LlmConfig {
+            rate_limits: RateLimitConfig {
+                max_concurrent: 0,
+                max_queue_wait_secs: 1,
+            },
+            ..config.clone()
+        };
+        assert!(limiter_for(&disabled).is_none());
+
+        let provider = LimitedProvider::new(Arc::new(MockLlmProvider::new()), a);
+        let request = CompletionRequest {
+            messages: vec![Message::user("hi")],
+            ..Default::default()
+        };
+        assert!(provider.complete(request).await.is_ok());
+        assert!(
+            limiter_stats()
+                .iter()
+                .any(|s| s.key == "limiter-test|default|OPENAI_API_KEY" && s.in_flight == 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_limits_resizes_live_limiter() {
+        let limiter = ProviderLimiter::new("resize", 2, Duration::from_millis(50));
+        let first = limiter.acquire().await.unwrap();
+        let second = limiter.acquire().await.unwrap();
+
+        limiter.set_limits(1, Duration::from_millis(50));
+        assert_eq!(limiter.stats().max_concurrent, 1);
+        drop(first);
+        drop(second);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let held = limiter.acquire().await.unwrap();
+        assert!(matches!(
+            limiter.acquire().await,
+            Err(LlmError::Saturated { .. })
+        ));
+
+        limiter.set_limits(3, Duration::from_millis(50));
+        let _a = limiter.acquire().await.unwrap();
+        let _b = limiter.acquire().await.unwrap();
+        assert_eq!(limiter.stats().in_flight, 3);
+        drop(held);
+    }
+}
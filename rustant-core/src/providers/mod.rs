@@ -10,6 +10,7 @@
 pub mod anthropic;
 pub mod failover;
 pub mod gemini;
+pub mod limiter;
 pub mod models;
 pub mod openai_compat;
 
@@ -25,13 +26,15 @@ pub use crate::config::RetryConfig;
 pub use anthropic::AnthropicProvider;
 pub use failover::{AuthProfile, CircuitBreaker, CircuitState, FailoverProvider};
 pub use gemini::GeminiProvider;
+pub use limiter::{LimitedProvider, LimiterStats, ProviderLimiter, limiter_stats};
 pub use models::ModelInfo;
 pub use openai_compat::OpenAiCompatibleProvider;
 
 /// Execute an async operation with exponential backoff retry on transient errors.
 ///
 /// Retries on `LlmError::RateLimited` (respects `retry_after_secs`), `LlmError::Streaming`,
-/// `LlmError::Connection`, and `LlmError::Timeout`. Permanent errors (auth, parse) return immediately.
+/// `LlmError::Connection`, and `LlmError::Timeout`. Permanent errors (auth, parse) and
+/// `LlmError::Saturated` (the request already waited in the limiter queue) return immediately.
 pub async fn with_retry<F, Fut, T>(config: &RetryConfig, operation: F) -> Result<T, LlmError>
 where
     F: Fn() -> Fut,
@@ -110,9 +113,10 @@ fn rand_simple() -> f64 {
 }
 
 /// Resolve the API key for a provider, checking the credential store first,
-/// then falling back to the environment variable.
+/// then the environment variable, then the store under the provider name
+/// (where `rustant auth migrate` puts keys moved off env vars).
 ///
-/// Returns the API key string, or an `LlmError::AuthFailed` if neither source has a key.
+/// Returns the API key string, or an `LlmError::AuthFailed` if no source has a key.
 pub fn resolve_api_key(
     config: &LlmConfig,
     cred_store: &dyn CredentialStore,
@@ -124,12 +128,18 @@ pub fn resolve_api_key(
         return Ok(key);
     }
     // 2. Fall back to env var
-    std::env::var(&config.api_key_env).map_err(|_| LlmError::AuthFailed {
-        provider: format!(
-            "env var '{}' not set and no credential store key found",
-            config.api_key_env
-        ),
-    })
+    if let Ok(key) = std::env::var(&config.api_key_env) {
+        return Ok(key);
+    }
+    // 3. Keys migrated off the env var
+    cred_store
+        .get_key(&config.provider)
+        .map_err(|_| LlmError::AuthFailed {
+            provider: format!(
+                "env var '{}' not set and no credential store key found",
+                config.api_key_env
+            ),
+        })
 }
 
 /// Resolve authentication for a provider, supporting both API keys and OAuth tokens.
@@ -144,43 +154,28 @@ pub async fn resolve_auth(
     cred_store: &dyn CredentialStore,
 ) -> Result<String, LlmError> {
     if config.auth_method == "oauth" {
-        let token = crate::oauth::load_oauth_token(cred_store, &config.provider)?;
-
-        if crate::oauth::is_token_expired(&token) {
-            if let Some(ref rt) = token.refresh_token {
-                let oauth_cfg = crate::oauth::oauth_config_for_provider(&config.provider)
-                    .ok_or_else(|| LlmError::OAuthFailed {
-                        message: format!(
-                            "OAuth not supported for provider '{}' — cannot refresh token",
-                            config.provider
-                        ),
-                    })?;
-                let new_token = crate::oauth::refresh_token(&oauth_cfg, rt).await?;
-                crate::oauth::store_oauth_token(cred_store, &config.provider, &new_token)?;
-                return Ok(new_token.access_token);
-            }
-            return Err(LlmError::OAuthFailed {
-                message: format!(
-                    "OAuth token for '{}' has expired and no refresh token is available. \
-                     Please re-authenticate with: rustant auth login {}",
-                    config.provider, config.provider
-                ),
-            });
-        }
-
-        Ok(token.access_token)
+        crate::oauth::resolve_access_token(cred_store, &config.provider).await
     } else {
         resolve_api_key(config, cred_store)
     }
 }
 
+/// Wrap a provider with the shared concurrency limiter for its credential.
+fn with_limiter(config: &LlmConfig, provider: Arc<dyn LlmProvider>) -> Arc<dyn LlmProvider> {
+    match limiter::limiter_for(config) {
+        Some(limiter) => Arc::new(LimitedProvider::new(provider, limiter)),
+        None => provider,
+    }
+}
+
 /// Create a single LLM provider based on the configuration.
 fn create_single_provider(config: &LlmConfig) -> Result<Arc<dyn LlmProvider>, LlmError> {
-    match config.provider.as_str() {
-        "anthropic" => Ok(Arc::new(AnthropicProvider::new(config)?)),
-        "gemini" => Ok(Arc::new(GeminiProvider::new(config)?)),
-        _ => Ok(Arc::new(OpenAiCompatibleProvider::new(config)?)),
-    }
+    let provider: Arc<dyn LlmProvider> = match config.provider.as_str() {
+        "anthropic" => Arc::new(AnthropicProvider::new(config)?),
+        "gemini" => Arc::new(GeminiProvider::new(config)?),
+        _ => Arc::new(OpenAiCompatibleProvider::new(config)?),
+    };
+    Ok(with_limiter(config, provider))
 }
 
 /// Create a single LLM provider using a pre-resolved API key or token.
@@ -188,13 +183,12 @@ fn create_single_provider_with_key(
     config: &LlmConfig,
     api_key: String,
 ) -> Result<Arc<dyn LlmProvider>, LlmError> {
-    match config.provider.as_str() {
-        "anthropic" => Ok(Arc::new(AnthropicProvider::new_with_key(config, api_key)?)),
-        "gemini" => Ok(Arc::new(GeminiProvider::new_with_key(config, api_key)?)),
-        _ => Ok(Arc::new(OpenAiCompatibleProvider::new_with_key(
-            config, api_key,
-        )?)),
-    }
+    let provider: Arc<dyn LlmProvider> = match config.provider.as_str() {
+        "anthropic" => Arc::new(AnthropicProvider::new_with_key(config, api_key)?),
+        "gemini" => Arc::new(GeminiProvider::new_with_key(config, api_key)?),
+        _ => Arc::new(OpenAiCompatibleProvider::new_with_key(config, api_key)?),
+    };
+    Ok(with_limiter(config, provider))
 }
 
 /// Create an LLM provider based on the configuration.
@@ -299,6 +293,44 @@ pub async fn create_provider_with_auth(
     )))
 }
 
+/// Create the provider that describes attached images for a model without
+/// vision, from `[llm] vision_fallback`. Returns `None` when none is
+/// configured or it fails to initialize.
+pub fn create_vision_provider(config: &LlmConfig) -> Option<Arc<dyn LlmProvider>> {
+    let vision = config.vision_fallback.as_ref()?;
+    let vision_config = LlmConfig {
+        provider: vision.provider.clone(),
+        model: vision.model.clone(),
+        api_key_env: vision.api_key_env.clone(),
+        base_url: vision.base_url.clone(),
+        api_key: None,
+        credential_store_key: None,
+        auth_method: String::new(),
+        fallback_providers: Vec::new(),
+        vision_fallback: None,
+        ..config.clone()
+    };
+    match create_single_provider(&vision_config) {
+        Ok(provider) if provider.supports_vision() => Some(provider),
+        Ok(provider) => {
+            tracing::warn!(
+                model = provider.model_name(),
+                "Vision fallback model does not accept images; ignoring it"
+            );
+            None
+        }
+        Err(e) => {
+            tracing::warn!(
+                provider = %vision.provider,
+                model = %vision.model,
+                error = %e,
+                "Vision fallback provider failed to initialize"
+            );
+            None
+        }
+    }
+}
+
 /// Create LLM providers for council members.
 ///
 /// Iterates over the council member configs, creates a provider for each,
@@ -352,10 +384,12 @@ mod tests {
             output_cost_per_million: 2.0,
             use_streaming: false,
             fallback_providers: Vec::new(),
+            vision_fallback: None,
             credential_store_key: None,
             auth_method: String::new(),
             api_key: None,
             retry: RetryConfig::default(),
+            rate_limits: crate::config::RateLimitConfig::default(),
         }
     }
 
@@ -482,6 +516,23 @@ mod tests {
         unsafe { std::env::remove_var("RUSTANT_RESOLVE_FALLBACK_KEY") };
     }
 
+    #[test]
+    fn test_resolve_api_key_falls_back_to_provider_account() {
+        use crate::credentials::InMemoryCredentialStore;
+
+        let store = InMemoryCredentialStore::new();
+        store.store_key("anthropic", "sk-migrated").unwrap();
+
+        let mut config = test_config("anthropic");
+        config.api_key_env = "RUSTANT_RESOLVE_UNSET_KEY".to_string();
+
+        let key = resolve_api_key(&config, &store).unwrap();
+        assert_eq!(key, "sk-migrated");
+
+        config.provider = "gemini".to_string();
+        assert!(resolve_api_key(&config, &store).is_err());
+    }
+
     #[test]
     fn test_is_retryable() {
         assert!(super::is_retryable(&LlmError::RateLimited {
@@ -497,6 +548,10 @@ mod tests {
         assert!(!super::is_retryable(&LlmError::AuthFailed {
             provider: "test".into()
         }));
+        assert!(!super::is_retryable(&LlmError::Saturated {
+            provider: "test".into(),
+            waited_ms: 1000
+        }));
         assert!(!super::is_retryable(&LlmError::ResponseParse {
             message: "bad json".into()
         }));
@@ -7,8 +7,8 @@ use crate::brain::{LlmProvider, TokenCounter};
 use crate::config::LlmConfig;
 use crate::error::LlmError;
 use crate::types::{
-    CompletionRequest, CompletionResponse, Content, Message, Role, StreamEvent, TokenUsage,
-    ToolDefinition,
+    CompletionRequest, CompletionResponse, Content, ImageSource, Message, Role, StreamEvent,
+    TokenUsage, ToolDefinition,
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -96,6 +96,34 @@ fn known_model_meta(model: &str) -> Option<ModelMeta> {
     }
 }
 
+/// Whether a model is known to accept `image_url` content parts.
+fn model_supports_vision(model: &str) -> bool {
+    let model = model.to_lowercase();
+    [
+        "gpt-4o",
+        "gpt-4-turbo",
+        "gpt-4.1",
+        "gpt-5",
+        "o1",
+        "o3",
+        "o4",
+    ]
+    .iter()
+    .any(|prefix| model.starts_with(prefix))
+        || model.contains("vision")
+        || model.contains("llava")
+}
+
+/// An OpenAI `image_url` content part carrying the image as a data URL.
+fn image_part(image: &ImageSource) -> Value {
+    json!({
+        "type": "image_url",
+        "image_url": {
+            "url": format!("data:{};base64,{}", image.media_type, image.data),
+        }
+    })
+}
+
 /// OpenAI-compatible LLM provider.
 pub struct OpenAiCompatibleProvider {
     client: Client,
@@ -188,6 +216,15 @@ impl OpenAiCompatibleProvider {
         })
     }
 
+    /// Native API root when this endpoint is an Ollama server (default port 11434).
+    fn ollama_api_base(&self) -> Option<String> {
+        if !self.base_url.contains(":11434") {
+            return None;
+        }
+        let base = self.base_url.trim_end_matches('/');
+        Some(base.strip_suffix("/v1").unwrap_or(base).to_string())
+    }
+
     /// Convert internal messages to OpenAI JSON format.
     fn messages_to_json(messages: &[Message]) -> Vec<Value> {
         messages
@@ -228,12 +265,19 @@ impl OpenAiCompatibleProvider {
                         "content": output,
                     }),
                     Content::MultiPart { parts } => {
-                        // Collect text parts and tool calls
+                        // Collect text parts, tool calls and images
                         let mut text_parts = Vec::new();
                         let mut tool_calls = Vec::new();
+                        let mut images = Vec::new();
                         for part in parts {
                             match part {
                                 Content::Text { text } => text_parts.push(text.clone()),
+                                Content::Attachment {
+                                    image: Some(image), ..
+                                } => images.push(image_part(image)),
+                                Content::Attachment { handle, .. } => {
+                                    text_parts.push(handle.reference())
+                                }
                                 Content::ToolCall {
                                     id,
                                     name,
@@ -257,6 +301,16 @@ impl OpenAiCompatibleProvider {
                                 "content": if text_parts.is_empty() { Value::Null } else { Value::String(text_parts.join("\n")) },
                                 "tool_calls": tool_calls,
                             })
+                        } else if !images.is_empty() {
+                            let mut content: Vec<Value> = text_parts
+                                .into_iter()
+                                .map(|text| json!({"type": "text", "text": text}))
+                                .collect();
+                            content.extend(images);
+                            json!({
+                                "role": role,
+                                "content": content,
+                            })
                         } else {
                             json!({
                                 "role": role,
@@ -264,6 +318,16 @@ impl OpenAiCompatibleProvider {
                             })
                         }
                     }
+                    Content::Attachment {
+                        image: Some(image), ..
+                    } => json!({
+                        "role": role,
+                        "content": [image_part(image)],
+                    }),
+                    Content::Attachment { handle, .. } => json!({
+                        "role": role,
+                        "content": handle.reference(),
+                    }),
                 }
             })
             .collect()
@@ -380,6 +444,7 @@ impl OpenAiCompatibleProvider {
             usage,
             model: resp_model,
             finish_reason,
+            cache_hit: None,
         })
     }
 
@@ -746,6 +811,10 @@ impl LlmProvider for OpenAiCompatibleProvider {
         self.supports_tools
     }
 
+    fn supports_vision(&self) -> bool {
+        model_supports_vision(&self.model)
+    }
+
     fn cost_per_token(&self) -> (f64, f64) {
         (self.cost_input, self.cost_output)
     }
@@ -753,6 +822,25 @@ impl LlmProvider for OpenAiCompatibleProvider {
     fn model_name(&self) -> &str {
         &self.model
     }
+
+    /// Count tokens with Ollama's `/api/tokenize` endpoint; other
+    /// OpenAI-compatible servers don't expose a tokenizer.
+    async fn count_tokens(&self, text: &str) -> Option<usize> {
+        let api_base = self.ollama_api_base()?;
+        let response = self
+            .client
+            .post(format!("{}/api/tokenize", api_base))
+            .json(&serde_json::json!({ "model": self.model, "content": text }))
+            .send()
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            debug!(status = %response.status(), "Ollama token counting unavailable");
+            return None;
+        }
+        let json: Value = response.json().await.ok()?;
+        json["tokens"].as_array().map(|tokens| tokens.len())
+    }
 }
 
 #[cfg(test)]
@@ -772,10 +860,12 @@ mod tests {
             output_cost_per_million: 10.0,
             use_streaming: false,
             fallback_providers: Vec::new(),
+            vision_fallback: None,
             credential_store_key: None,
             auth_method: String::new(),
             api_key: None,
             retry: crate::config::RetryConfig::default(),
+            rate_limits: crate::config::RateLimitConfig::default(),
         }
     }
 
@@ -2,7 +2,15 @@
 //!
 //! Enables reviewing past agent executions by stepping through recorded
 //! trace events, providing context at each step about what happened
-//! and why.
+//! and why. The [`session`] submodule replays saved sessions turn by turn,
+//! with full prompts, and re-executes them against the current model.
+
+pub mod session;
+
+pub use session::{
+    Divergence, DivergenceKind, RecordedToolCall, RecordedTurn, ReexecutionReport, SafetyDecision,
+    SessionRecorder, SessionRecording, SessionReplay,
+};
 
 use crate::audit::{AuditStore, ExecutionTrace, TraceEvent, TraceEventKind};
 use crate::types::{CostEstimate, TokenUsage};
@@ -26,6 +34,14 @@ pub enum ReplayError {
     BookmarkNotFound(usize),
     #[error("empty trace: no events to replay")]
     EmptyTrace,
+    #[error("no session found matching: '{0}'")]
+    SessionNotFound(String),
+    #[error(
+        "session '{0}' was recorded before replay support; only sessions saved since then can be replayed"
+    )]
+    NotRecorded(String),
+    #[error("failed to load recording: {0}")]
+    LoadFailed(String),
 }
 
 // ---------------------------------------------------------------------------
@@ -0,0 +1,847 @@
+//! Turn-level session recordings for step-through replay and re-execution.
+//!
+//! The agent records every LLM call as a [`RecordedTurn`]: the assembled
+//! prompt, the model's response, and each tool call with its arguments,
+//! output and safety decision. Recordings are saved next to the session
+//! data, stepped through with [`SessionReplay`], and re-run against the
+//! current model with [`ReexecutionReport`] collecting the divergences.
+
+use super::ReplayError;
+use crate::types::{Content, Message, Role, TokenUsage};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Recording format version; older or newer recordings are refused.
+pub const RECORDING_VERSION: u32 = 1;
+
+/// Word-overlap similarity below which replayed text counts as divergent.
+pub const TEXT_DIVERGENCE_THRESHOLD: f64 = 0.5;
+
+/// Longest tool output or response text shown per turn before truncation.
+const DISPLAY_LIMIT: usize = 600;
+
+// ---------------------------------------------------------------------------
+// Recording types
+// ---------------------------------------------------------------------------
+
+/// How the safety layer handled a tool call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum SafetyDecision {
+    /// Allowed by policy without asking.
+    Allowed,
+    /// Approved by the user or a capability grant.
+    Approved { all_similar: bool },
+    /// Refused by policy, a contract, a capability limit, or the user.
+    Denied { reason: String },
+    /// Not subject to safety checks (e.g. `ask_user`).
+    Unchecked,
+}
+
+impl std::fmt::Display for SafetyDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SafetyDecision::Allowed => write!(f, "allowed by policy"),
+            SafetyDecision::Approved { all_similar: false } => write!(f, "approved"),
+            SafetyDecision::Approved { all_similar: true } => {
+                write!(f, "approved (all similar)")
+            }
+            SafetyDecision::Denied { reason } => write!(f, "denied: {}", reason),
+            SafetyDecision::Unchecked => write!(f, "not checked"),
+        }
+    }
+}
+
+/// One tool call made during a turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedToolCall {
+    pub call_id: String,
+    /// Tool the model asked for.
+    pub name: String,
+    /// Tool actually run, when auto-routing replaced the model's choice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routed_to: Option<String>,
+    pub arguments: serde_json::Value,
+    /// Tool output, or the error text returned to the model.
+    pub output: String,
+    pub is_error: bool,
+    pub safety: SafetyDecision,
+    pub duration_ms: u64,
+}
+
+/// One LLM call: the prompt sent, the response, and the tools it ran.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedTurn {
+    pub task_id: Uuid,
+    /// The user task this turn belongs to.
+    pub task: String,
+    /// Agent loop iteration within the task (1-based).
+    pub iteration: usize,
+    pub started_at: DateTime<Utc>,
+    pub model: String,
+    /// Indices into [`SessionRecording::messages`], system prompt first.
+    pub prompt: Vec<usize>,
+    /// Names of the tools offered to the model.
+    pub tools: Vec<String>,
+    pub response: Content,
+    pub usage: TokenUsage,
+    pub tool_calls: Vec<RecordedToolCall>,
+}
+
+/// Every LLM turn of a session, with prompt messages stored once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecording {
+    pub version: u32,
+    /// Distinct messages referenced by turn prompts.
+    pub messages: Vec<Message>,
+    pub turns: Vec<RecordedTurn>,
+}
+
+impl Default for SessionRecording {
+    fn default() -> Self {
+        Self {
+            version: RECORDING_VERSION,
+            messages: Vec::new(),
+            turns: Vec::new(),
+        }
+    }
+}
+
+/// Counts and excerpts describing an assembled prompt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptSummary {
+    pub system_chars: usize,
+    pub user_messages: usize,
+    pub assistant_messages: usize,
+    pub tool_results: usize,
+    /// Rough size in tokens (4 characters per token).
+    pub approx_tokens: usize,
+    pub latest_user: Option<String>,
+}
+
+impl SessionRecording {
+    /// Parse a saved recording, refusing files from another format version.
+    pub fn from_json(name: &str, data: &[u8]) -> Result<Self, ReplayError> {
+        let value: serde_json::Value = serde_json::from_slice(data)
+            .map_err(|e| ReplayError::LoadFailed(format!("{}: {}", name, e)))?;
+        if value.get("version").and_then(|v| v.as_u64()) != Some(RECORDING_VERSION as u64) {
+            return Err(ReplayError::NotRecorded(name.to_string()));
+        }
+        let recording: Self = serde_json::from_value(value)
+            .map_err(|e| ReplayError::LoadFailed(format!("{}: {}", name, e)))?;
+        recording.validate(name)?;
+        Ok(recording)
+    }
+
+    /// Check that every prompt index points at a stored message.
+    fn validate(&self, name: &str) -> Result<(), ReplayError> {
+        let broken = self
+            .turns
+            .iter()
+            .any(|t| t.prompt.iter().any(|&i| i >= self.messages.len()));
+        if broken {
+            return Err(ReplayError::LoadFailed(format!(
+                "{}: prompt references a missing message",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    /// The full prompt of a turn, system message first.
+    pub fn prompt(&self, turn: usize) -> Result<Vec<&Message>, ReplayError> {
+        let turn = self.turn(turn)?;
+        Ok(turn
+            .prompt
+            .iter()
+            .filter_map(|&i| self.messages.get(i))
+            .collect())
+    }
+
+    /// A turn's prompt without its system message, ready to re-send
+    /// through a [`Brain`](crate::brain::Brain) with the current system prompt.
+    pub fn conversation(&self, turn: usize) -> Result<Vec<Message>, ReplayError> {
+        Ok(self
+            .prompt(turn)?
+            .into_iter()
+            .filter(|m| m.role != Role::System)
+            .cloned()
+            .collect())
+    }
+
+    fn turn(&self, index: usize) -> Result<&RecordedTurn, ReplayError> {
+        self.turns.get(index).ok_or(ReplayError::OutOfBounds {
+            position: index,
+            total: self.turns.len(),
+        })
+    }
+
+    /// Summarize the prompt of a turn.
+    pub fn summarize_prompt(&self, turn: usize) -> Result<PromptSummary, ReplayError> {
+        let mut summary = PromptSummary {
+            system_chars: 0,
+            user_messages: 0,
+            assistant_messages: 0,
+            tool_results: 0,
+            approx_tokens: 0,
+            latest_user: None,
+        };
+        let mut chars = 0;
+        for message in self.prompt(turn)? {
+            let text = content_text(&message.content);
+            chars += text.len();
+            match message.role {
+                Role::System => summary.system_chars += text.len(),
+                Role::User => {
+                    summary.user_messages += 1;
+                    summary.latest_user = Some(text);
+                }
+                Role::Assistant => summary.assistant_messages += 1,
+                Role::Tool => summary.tool_results += 1,
+            }
+        }
+        summary.approx_tokens = chars.div_ceil(4);
+        Ok(summary)
+    }
+
+    /// Human-readable view of one turn: prompt summary, response, tool
+    /// calls with arguments and output, and safety decisions.
+    pub fn render_turn(&self, index: usize) -> Result<String, ReplayError> {
+        let turn = self.turn(index)?;
+        let summary = self.summarize_prompt(index)?;
+        let mut out = format!(
+            "Turn {}/{} — {} (iteration {}, {})\n",
+            index + 1,
+            self.turns.len(),
+            turn.model,
+            turn.iteration,
+            turn.started_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        out.push_str(&format!("Task: {}\n\n", truncate(&turn.task, 200)));
+        out.push_str(&format!(
+            "Prompt: ~{} tokens — system prompt {} chars, {} user, {} assistant, {} tool results, {} tools offered\n",
+            summary.approx_tokens,
+            summary.system_chars,
+            summary.user_messages,
+            summary.assistant_messages,
+            summary.tool_results,
+            turn.tools.len()
+        ));
+        if let Some(latest) = &summary.latest_user {
+            out.push_str(&format!(
+                "  Latest user message: {}\n",
+                truncate(latest, 200)
+            ));
+        }
+        out.push_str(&format!(
+            "\nResponse ({} in / {} out tokens):\n",
+            turn.usage.input_tokens, turn.usage.output_tokens
+        ));
+        let text = content_text(&turn.response);
+        if !text.is_empty() {
+            out.push_str(&format!("  {}\n", truncate(&text, DISPLAY_LIMIT)));
+        }
+        for call in &turn.tool_calls {
+            out.push_str(&format!("\nTool call: {}", call.name));
+            if let Some(routed) = &call.routed_to {
+                out.push_str(&format!(" (routed to {})", routed));
+            }
+            out.push_str(&format!(" [{}ms]\n", call.duration_ms));
+            out.push_str(&format!(
+                "  Arguments: {}\n",
+                truncate(&call.arguments.to_string(), DISPLAY_LIMIT)
+            ));
+            out.push_str(&format!("  Safety: {}\n", call.safety));
+            out.push_str(&format!(
+                "  {}: {}\n",
+                if call.is_error { "Error" } else { "Output" },
+                truncate(&call.output, DISPLAY_LIMIT)
+            ));
+        }
+        Ok(out)
+    }
+
+    /// Write a turn's full prompt, as sent to the model, to `path` as JSON.
+    pub fn dump_prompt(&self, turn: usize, path: &Path) -> Result<(), ReplayError> {
+        let prompt = self.prompt(turn)?;
+        let json = serde_json::to_string_pretty(&prompt)
+            .map_err(|e| ReplayError::LoadFailed(e.to_string()))?;
+        std::fs::write(path, json)
+            .map_err(|e| ReplayError::LoadFailed(format!("{}: {}", path.display(), e)))
+    }
+
+    /// The turns started before `cut`, keeping only the messages they
+    /// reference. Used when a session is forked at an earlier turn.
+    pub fn truncated(&self, cut: DateTime<Utc>) -> Self {
+        let mut remap: HashMap<usize, usize> = HashMap::new();
+        let mut messages = Vec::new();
+        let turns = self
+            .turns
+            .iter()
+            .filter(|t| t.started_at < cut)
+            .map(|t| {
+                let mut turn = t.clone();
+                for i in &mut turn.prompt {
+                    let old = *i;
+                    *i = *remap.entry(old).or_insert_with(|| {
+                        messages.push(self.messages[old].clone());
+                        messages.len() - 1
+                    });
+                }
+                turn
+            })
+            .collect();
+        Self {
+            version: self.version,
+            messages,
+            turns,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SessionRecorder
+// ---------------------------------------------------------------------------
+
+/// Builds a [`SessionRecording`] as the agent runs.
+#[derive(Debug, Default)]
+pub struct SessionRecorder {
+    recording: SessionRecording,
+    /// Message content hash → indices into `recording.messages`.
+    index: HashMap<u64, Vec<usize>>,
+}
+
+impl SessionRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Continue an existing recording (e.g. after resuming a session).
+    pub fn from_recording(recording: SessionRecording) -> Self {
+        let mut index: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (i, message) in recording.messages.iter().enumerate() {
+            index.entry(message_key(message)).or_default().push(i);
+        }
+        Self { recording, index }
+    }
+
+    /// The recording so far.
+    pub fn recording(&self) -> &SessionRecording {
+        &self.recording
+    }
+
+    /// Record an LLM call. `prompt` is the assembled message list sent to
+    /// the model, system prompt included.
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_turn(
+        &mut self,
+        task_id: Uuid,
+        task: &str,
+        iteration: usize,
+        model: &str,
+        prompt: &[Message],
+        tools: Vec<String>,
+        response: &Content,
+        usage: &TokenUsage,
+    ) {
+        let prompt = prompt.iter().map(|m| self.intern(m)).collect();
+        self.recording.turns.push(RecordedTurn {
+            task_id,
+            task: task.to_string(),
+            iteration,
+            started_at: Utc::now(),
+            model: model.to_string(),
+            prompt,
+            tools,
+            response: response.clone(),
+            usage: *usage,
+            tool_calls: Vec::new(),
+        });
+    }
+
+    /// Attach a tool call to the latest turn.
+    pub fn record_tool_call(&mut self, call: RecordedToolCall) {
+        if let Some(turn) = self.recording.turns.last_mut() {
+            turn.tool_calls.push(call);
+        }
+    }
+
+    fn intern(&mut self, message: &Message) -> usize {
+        let key = message_key(message);
+        let candidates = self.index.entry(key).or_default();
+        if let Some(&i) = candidates.iter().find(|&&i| {
+            let stored = &self.recording.messages[i];
+            stored.role == message.role && stored.content == message.content
+        }) {
+            return i;
+        }
+        let i = self.recording.messages.len();
+        self.recording.messages.push(message.clone());
+        candidates.push(i);
+        i
+    }
+}
+
+/// Hash of a message's role and content (ids and timestamps differ between
+/// copies of the same system prompt).
+fn message_key(message: &Message) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    message.role.hash(&mut hasher);
+    serde_json::to_string(&message.content)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+// ---------------------------------------------------------------------------
+// SessionReplay
+// ---------------------------------------------------------------------------
+
+/// Turn-by-turn playback of a [`SessionRecording`].
+pub struct SessionReplay {
+    recording: SessionRecording,
+    position: usize,
+}
+
+impl SessionReplay {
+    /// Start at the first turn. Fails for recordings without turns.
+    pub fn new(recording: SessionRecording) -> Result<Self, ReplayError> {
+        if recording.turns.is_empty() {
+            return Err(ReplayError::EmptyTrace);
+        }
+        Ok(Self {
+            recording,
+            position: 0,
+        })
+    }
+
+    /// Current turn index (0-based).
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Number of recorded turns.
+    pub fn total_turns(&self) -> usize {
+        self.recording.turns.len()
+    }
+
+    /// The turn at the current position.
+    pub fn current(&self) -> &RecordedTurn {
+        &self.recording.turns[self.position]
+    }
+
+    /// Move to the next turn, or `None` at the end.
+    pub fn step_forward(&mut self) -> Option<&RecordedTurn> {
+        if self.position + 1 < self.total_turns() {
+            self.position += 1;
+            Some(self.current())
+        } else {
+            None
+        }
+    }
+
+    /// Move to the previous turn, or `None` at the start.
+    pub fn step_backward(&mut self) -> Option<&RecordedTurn> {
+        if self.position > 0 {
+            self.position -= 1;
+            Some(self.current())
+        } else {
+            None
+        }
+    }
+
+    /// Jump to a turn (0-based).
+    pub fn seek(&mut self, position: usize) -> Result<&RecordedTurn, ReplayError> {
+        if position >= self.total_turns() {
+            return Err(ReplayError::OutOfBounds {
+                position,
+                total: self.total_turns(),
+            });
+        }
+        self.position = position;
+        Ok(self.current())
+    }
+
+    /// Render the current turn.
+    pub fn render_current(&self) -> String {
+        self.recording
+            .render_turn(self.position)
+            .unwrap_or_default()
+    }
+
+    /// Write the current turn's full prompt to `path`.
+    pub fn dump_current_prompt(&self, path: &Path) -> Result<(), ReplayError> {
+        self.recording.dump_prompt(self.position, path)
+    }
+
+    /// The recording being replayed.
+    pub fn recording(&self) -> &SessionRecording {
+        &self.recording
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Re-execution
+// ---------------------------------------------------------------------------
+
+/// How a re-executed turn differs from the recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DivergenceKind {
+    /// The model chose different tools (or tools instead of text).
+    ToolChoice {
+        recorded: Vec<String>,
+        replayed: Vec<String>,
+    },
+    /// Same tool, different arguments.
+    Arguments { tool: String },
+    /// Text response changed materially.
+    Text { similarity: f64 },
+}
+
+/// A divergence at one turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Divergence {
+    /// Turn index (0-based).
+    pub turn: usize,
+    pub kind: DivergenceKind,
+}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Turn {}: ", self.turn + 1)?;
+        match &self.kind {
+            DivergenceKind::ToolChoice { recorded, replayed } => write!(
+                f,
+                "tools [{}] -> [{}]",
+                recorded.join(", "),
+                replayed.join(", ")
+            ),
+            DivergenceKind::Arguments { tool } => write!(f, "different arguments to {}", tool),
+            DivergenceKind::Text { similarity } => {
+                write!(f, "text changed ({:.0}% word overlap)", similarity * 100.0)
+            }
+        }
+    }
+}
+
+/// Outcome of re-running a recording against the current model and config.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReexecutionReport {
+    pub model: String,
+    pub turns_compared: usize,
+    pub divergences: Vec<Divergence>,
+    pub usage: TokenUsage,
+}
+
+impl ReexecutionReport {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Compare a replayed response with the recorded one.
+    pub fn compare(
+        &mut self,
+        turn: usize,
+        recorded: &Content,
+        replayed: &Content,
+        usage: &TokenUsage,
+    ) {
+        self.turns_compared += 1;
+        self.usage.accumulate(usage);
+        self.divergences
+            .extend(compare_responses(turn, recorded, replayed));
+    }
+
+    /// Turns with at least one divergence.
+    pub fn divergent_turns(&self) -> usize {
+        self.divergences
+            .iter()
+            .map(|d| d.turn)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+}
+
+impl std::fmt::Display for ReexecutionReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Re-executed {} turns with {}: {} diverged ({} in / {} out tokens)",
+            self.turns_compared,
+            self.model,
+            self.divergent_turns(),
+            self.usage.input_tokens,
+            self.usage.output_tokens
+        )?;
+        for divergence in &self.divergences {
+            writeln!(f, "  {}", divergence)?;
+        }
+        Ok(())
+    }
+}
+
+/// Divergences between a recorded and a replayed response.
+pub fn compare_responses(turn: usize, recorded: &Content, replayed: &Content) -> Vec<Divergence> {
+    let recorded_calls = tool_calls(recorded);
+    let replayed_calls = tool_calls(replayed);
+    let names = |calls: &[(&str, &serde_json::Value)]| -> Vec<String> {
+        calls.iter().map(|(n, _)| n.to_string()).collect()
+    };
+
+    let mut divergences = Vec::new();
+    if names(&recorded_calls) != names(&replayed_calls) {
+        divergences.push(Divergence {
+            turn,
+            kind: DivergenceKind::ToolChoice {
+                recorded: names(&recorded_calls),
+                replayed: names(&replayed_calls),
+            },
+        });
+    } else {
+        for ((name, a), (_, b)) in recorded_calls.iter().zip(&replayed_calls) {
+            if a != b {
+                divergences.push(Divergence {
+                    turn,
+                    kind: DivergenceKind::Arguments {
+                        tool: name.to_string(),
+                    },
+                });
+            }
+        }
+    }
+
+    let (a, b) = (content_text(recorded), content_text(replayed));
+    if !(a.trim().is_empty() && b.trim().is_empty()) {
+        let similarity = text_similarity(&a, &b);
+        if similarity < TEXT_DIVERGENCE_THRESHOLD {
+            divergences.push(Divergence {
+                turn,
+                kind: DivergenceKind::Text { similarity },
+            });
+        }
+    }
+    divergences
+}
+
+/// Jaccard similarity of the lowercase word sets of two texts.
+fn text_similarity(a: &str, b: &str) -> f64 {
+    let words = |s: &str| -> HashSet<String> {
+        s.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+fn tool_calls(content: &Content) -> Vec<(&str, &serde_json::Value)> {
+    match content {
+        Content::ToolCall {
+            name, arguments, ..
+        } => vec![(name.as_str(), arguments)],
+        Content::MultiPart { parts } => parts.iter().flat_map(tool_calls).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The text of a message content, joining multi-part text and tool output.
+fn content_text(content: &Content) -> String {
+    match content {
+        Content::Text { text } => text.clone(),
+        Content::ToolResult { output, .. } => output.clone(),
+        Content::ToolCall { .. } => String::new(),
+        Content::Attachment { handle, .. } => handle.reference(),
+        Content::MultiPart { parts } => parts
+            .iter()
+            .map(content_text)
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}… ({} chars)", &text[..end], text.chars().count()),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn two_turn_recording() -> SessionRecording {
+        let mut recorder = SessionRecorder::new();
+        let task_id = Uuid::new_v4();
+        let user = Message::user("Clean up the build directory");
+        let call = Content::tool_call("c1", "file_delete", json!({"path": "build/old.o"}));
+        recorder.record_turn(
+            task_id,
+            "Clean up the build directory",
+            1,
+            "gpt-4o",
+            &[Message::system("You are Rustant."), user.clone()],
+            vec!["file_delete".to_string()],
+            &call,
+            &TokenUsage {
+                input_tokens: 120,
+                output_tokens: 20,
+            },
+        );
+        recorder.record_tool_call(RecordedToolCall {
+            call_id: "c1".into(),
+            name: "file_delete".into(),
+            routed_to: None,
+            arguments: json!({"path": "build/old.o"}),
+            output: "Deleted build/old.o".into(),
+            is_error: false,
+            safety: SafetyDecision::Approved { all_similar: false },
+            duration_ms: 3,
+        });
+        recorder.record_turn(
+            task_id,
+            "Clean up the build directory",
+            2,
+            "gpt-4o",
+            &[
+                Message::system("You are Rustant."),
+                user,
+                Message::new(Role::Assistant, call),
+                Message::tool_result("c1", "Deleted build/old.o", false),
+            ],
+            vec!["file_delete".to_string()],
+            &Content::text("Removed the stale object file."),
+            &TokenUsage {
+                input_tokens: 150,
+                output_tokens: 10,
+            },
+        );
+        recorder.recording().clone()
+    }
+
+    #[test]
+    fn test_recorder_stores_shared_messages_once() {
+        let recording = two_turn_recording();
+        // system + user shared by both turns, plus the call and its result
+        assert_eq!(recording.messages.len(), 4);
+        assert_eq!(recording.turns[0].prompt, vec![0, 1]);
+        assert_eq!(recording.turns[1].prompt, vec![0, 1, 2, 3]);
+        assert_eq!(recording.conversation(1).unwrap().len(), 3);
+
+        let summary = recording.summarize_prompt(1).unwrap();
+        assert_eq!(summary.user_messages, 1);
+        assert_eq!(summary.tool_results, 1);
+        assert_eq!(
+            summary.latest_user.as_deref(),
+            Some("Clean up the build directory")
+        );
+    }
+
+    #[test]
+    fn test_render_turn_shows_tool_call_and_safety() {
+        let recording = two_turn_recording();
+        let text = recording.render_turn(0).unwrap();
+        assert!(text.contains("Turn 1/2"));
+        assert!(text.contains("Tool call: file_delete"));
+        assert!(text.contains("build/old.o"));
+        assert!(text.contains("Safety: approved"));
+        assert!(text.contains("Output: Deleted build/old.o"));
+    }
+
+    #[test]
+    fn test_session_replay_stepping_and_dump() {
+        let mut replay = SessionReplay::new(two_turn_recording()).unwrap();
+        assert!(replay.step_backward().is_none());
+        assert_eq!(replay.step_forward().unwrap().iteration, 2);
+        assert!(replay.step_forward().is_none());
+        assert!(replay.seek(5).is_err());
+        replay.seek(0).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompt.json");
+        replay.dump_current_prompt(&path).unwrap();
+        let dumped: Vec<Message> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(dumped.len(), 2);
+        assert_eq!(dumped[0].role, Role::System);
+    }
+
+    #[test]
+    fn test_recording_without_version_is_refused() {
+        let legacy = json!({"messages": [], "turns": []}).to_string();
+        let err = SessionRecording::from_json("yesterday", legacy.as_bytes()).unwrap_err();
+        assert!(matches!(err, ReplayError::NotRecorded(_)));
+        assert!(err.to_string().contains("before replay support"));
+
+        let current = serde_json::to_vec(&two_turn_recording()).unwrap();
+        let loaded = SessionRecording::from_json("today", &current).unwrap();
+        assert_eq!(loaded.turns.len(), 2);
+    }
+
+    #[test]
+    fn test_compare_responses_detects_divergence() {
+        let recorded = Content::tool_call("c1", "file_delete", json!({"path": "a"}));
+        let same = Content::tool_call("c9", "file_delete", json!({"path": "a"}));
+        assert!(compare_responses(0, &recorded, &same).is_empty());
+
+        let other_args = Content::tool_call("c9", "file_delete", json!({"path": "b"}));
+        assert_eq!(
+            compare_responses(0, &recorded, &other_args)[0].kind,
+            DivergenceKind::Arguments {
+                tool: "file_delete".into()
+            }
+        );
+
+        let text = Content::text("I would rather not delete anything.");
+        let divergences = compare_responses(3, &recorded, &text);
+        assert!(matches!(
+            divergences[0].kind,
+            DivergenceKind::ToolChoice { .. }
+        ));
+        assert_eq!(divergences[0].turn, 3);
+
+        let a = Content::text("Removed the stale object file.");
+        let b = Content::text("I removed the stale object file.");
+        assert!(compare_responses(0, &a, &b).is_empty());
+        let c = Content::text("The build directory is already clean; nothing to do.");
+        assert!(matches!(
+            compare_responses(0, &a, &c)[0].kind,
+            DivergenceKind::Text { .. }
+        ));
+    }
+
+    #[test]
+    fn test_reexecution_report_counts_turns() {
+        let mut report = ReexecutionReport::new("gpt-4o-mini");
+        let usage = TokenUsage {
+            input_tokens: 10,
+            output_tokens: 5,
+        };
+        report.compare(0, &Content::text("done"), &Content::text("done"), &usage);
+        report.compare(
+            1,
+            &Content::text("done"),
+            &Content::tool_call("c", "shell_exec", json!({})),
+            &usage,
+        );
+        assert_eq!(report.turns_compared, 2);
+        assert_eq!(report.divergent_turns(), 1);
+        assert_eq!(report.usage.input_tokens, 20);
+        assert!(report.to_string().contains("1 diverged"));
+    }
+}
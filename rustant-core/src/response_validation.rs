@@ -0,0 +1,261 @@
+//! Validation of tool calls in LLM responses, before they are dispatched.
+//!
+//! Models sometimes call tools that do not exist (`file_edit`,
+//! `search_code`). The agent matches an unknown name against the registered
+//! tools and their aliases, by edit distance and by shared name words. When
+//! one tool is clearly closest and the call's arguments fit its schema, the
+//! call is corrected and logged. Otherwise the model gets a short message
+//! naming the closest tools with their one-line descriptions, instead of
+//! the whole tool catalog. Arguments of a corrected call are then checked
+//! by [`crate::tool_validation`] like any other call's.
+
+use crate::types::ToolDefinition;
+
+/// Suggestions listed in a corrective message at most.
+const MAX_SUGGESTIONS: usize = 4;
+
+/// Largest distance at which a tool is suggested.
+const MAX_SUGGEST_DISTANCE: usize = 4;
+
+/// Largest distance at which a call is corrected without asking the model.
+const MAX_CORRECT_DISTANCE: usize = 2;
+
+/// Longest one-line description shown for a suggestion.
+const MAX_SUMMARY_CHARS: usize = 100;
+
+/// A registered tool that an unknown name may have meant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolSuggestion {
+    /// The registered tool's name.
+    pub name: String,
+    /// The first sentence of its description.
+    pub summary: String,
+    /// How far the called name is from the tool's name or alias.
+    pub distance: usize,
+}
+
+/// Calls to unknown tools made by one model in a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UnknownToolCounts {
+    /// Calls corrected to a registered tool.
+    pub corrected: u64,
+    /// Calls answered with a corrective message.
+    pub rejected: u64,
+}
+
+/// The tools `called` most likely meant, closest first.
+///
+/// `candidates` pairs each name to compare against — a tool's own name or
+/// one of its aliases — with the tool it stands for.
+pub fn closest_tools<'a>(
+    called: &str,
+    candidates: impl IntoIterator<Item = (&'a str, &'a ToolDefinition)>,
+) -> Vec<ToolSuggestion> {
+    let mut suggestions: Vec<ToolSuggestion> = Vec::new();
+    for (name, tool) in candidates {
+        let distance = name_distance(called, name);
+        if distance > MAX_SUGGEST_DISTANCE {
+            continue;
+        }
+        match suggestions.iter_mut().find(|s| s.name == tool.name) {
+            Some(existing) => existing.distance = existing.distance.min(distance),
+            None => suggestions.push(ToolSuggestion {
+                name: tool.name.clone(),
+                summary: summary(&tool.description),
+                distance,
+            }),
+        }
+    }
+    suggestions.sort_by(|a, b| a.distance.cmp(&b.distance).then(a.name.cmp(&b.name)));
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// The suggestion to correct a call to, if one is close and clearly closer
+/// than the rest.
+pub fn unambiguous(suggestions: &[ToolSuggestion]) -> Option<&ToolSuggestion> {
+    let best = suggestions.first()?;
+    let clear = suggestions
+        .get(1)
+        .is_none_or(|second| second.distance > best.distance);
+    (best.distance <= MAX_CORRECT_DISTANCE && clear).then_some(best)
+}
+
+/// What the model is told after calling an unknown tool.
+pub fn corrective_message(suggestions: &[ToolSuggestion]) -> String {
+    if suggestions.is_empty() {
+        return "no tool has a similar name; call only the tools offered in this request"
+            .to_string();
+    }
+    let lines: Vec<String> = suggestions
+        .iter()
+        .map(|s| format!("- {}: {}", s.name, s.summary))
+        .collect();
+    format!(
+        "the closest tools are:\n{}\nCall one of them with its own arguments.",
+        lines.join("\n")
+    )
+}
+
+/// How far a called name is from a tool name: the smaller of their edit
+/// distance and a distance over the words of the names, so `search_code`
+/// is close to `codebase_search`. `usize::MAX` when the names are unrelated.
+pub fn name_distance(called: &str, tool: &str) -> usize {
+    let called = normalize(called);
+    let tool = normalize(tool);
+    let edits = edit_distance(&called, &tool);
+    // Short names are a few edits from anything.
+    let edits = if edits <= called.len() / 2 {
+        edits
+    } else {
+        usize::MAX
+    };
+    edits.min(word_distance(&called, &tool))
+}
+
+fn normalize(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string()
+}
+
+/// One more than the number of words either name has that the other lacks,
+/// where a word matches one it is a prefix of (`exec` and `execute`).
+/// `usize::MAX` when no word matches.
+fn word_distance(a: &str, b: &str) -> usize {
+    let words = |s: &'_ str| -> Vec<String> {
+        s.split('_')
+            .filter(|w| !w.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    let matches = |w: &String, other: &[String]| {
+        other.iter().any(|o| {
+            w == o
+                || (w.len().min(o.len()) >= 3
+                    && (o.starts_with(w.as_str()) || w.starts_with(o.as_str())))
+        })
+    };
+    let unmatched_a = a.iter().filter(|w| !matches(w, &b)).count();
+    let unmatched_b = b.iter().filter(|w| !matches(w, &a)).count();
+    if unmatched_a == a.len() {
+        return usize::MAX;
+    }
+    1 + unmatched_a + unmatched_b
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// The first sentence or line of a tool description, shortened.
+fn summary(description: &str) -> String {
+    let first = description.lines().next().unwrap_or("").trim();
+    let sentence = first
+        .find(". ")
+        .map_or(first, |end| &first[..=end])
+        .trim_end_matches('.');
+    if sentence.chars().count() > MAX_SUMMARY_CHARS {
+        let cut: String = sentence.chars().take(MAX_SUMMARY_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        sentence.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, description: &str) -> ToolDefinition {
+        ToolDefinition {
+            name: name.to_string(),
+            description: description.to_string(),
+            parameters: serde_json::json!({"type": "object"}),
+        }
+    }
+
+    fn registry() -> Vec<ToolDefinition> {
+        vec![
+            tool(
+                "file_read",
+                "Read the contents of a file. Supports line ranges.",
+            ),
+            tool("file_write", "Write content to a file."),
+            tool("file_patch", "Apply a patch to a file."),
+            tool("smart_edit", "Edit a file by describing the change."),
+            tool("codebase_search", "Search the project codebase."),
+            tool("shell_exec", "Run a shell command."),
+        ]
+    }
+
+    fn suggest(called: &str, tools: &[ToolDefinition]) -> Vec<ToolSuggestion> {
+        closest_tools(called, tools.iter().map(|t| (t.name.as_str(), t)))
+    }
+
+    #[test]
+    fn test_unambiguous_names_are_corrected() {
+        let tools = registry();
+        for (called, expected) in [
+            ("file_raed", "file_read"),
+            ("search_code", "codebase_search"),
+            ("shell_execute", "shell_exec"),
+            ("read_file", "file_read"),
+        ] {
+            let suggestions = suggest(called, &tools);
+            assert_eq!(
+                unambiguous(&suggestions).map(|s| s.name.as_str()),
+                Some(expected),
+                "{} -> {:?}",
+                called,
+                suggestions
+            );
+        }
+    }
+
+    #[test]
+    fn test_ambiguous_names_get_suggestions() {
+        let tools = registry();
+        let suggestions = suggest("file_edit", &tools);
+        assert!(unambiguous(&suggestions).is_none());
+        let names: Vec<&str> = suggestions.iter().map(|s| s.name.as_str()).collect();
+        assert!(names.contains(&"smart_edit"));
+        assert!(names.contains(&"file_write"));
+        assert!(suggestions.len() <= MAX_SUGGESTIONS);
+
+        let message = corrective_message(&suggestions);
+        assert!(message.contains("- file_read: Read the contents of a file\n"));
+        assert!(!message.contains("Supports line ranges"));
+        assert!(suggest("translate_poem", &tools).is_empty());
+    }
+
+    #[test]
+    fn test_aliases_suggest_their_tool() {
+        let app_control = tool("app_control", "Control applications.");
+        let candidates = [
+            ("app_control", &app_control),
+            ("macos_app_control", &app_control),
+        ];
+        let suggestions = closest_tools("macos_app_contrl", candidates);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].name, "app_control");
+        assert_eq!(suggestions[0].distance, 1);
+    }
+}
@@ -7,13 +7,19 @@
 //! 4. Output validation
 //! 5. Audit logging
 
+use crate::capabilities::{
+    Capability, CapabilityCheck, CapabilityDenial, CapabilityGrant, CapabilityPolicy,
+};
 use crate::config::{ApprovalMode, MessagePriority, SafetyConfig};
+use crate::contracts::{ContractSet, ContractViolation};
 use crate::injection::{InjectionDetector, InjectionScanResult, Severity as InjectionSeverity};
-use crate::types::RiskLevel;
+use crate::trust::{TrustError, TrustEventKind, TrustLedger, TrustPolicy, TrustScope};
+use crate::types::{RiskLevel, ToolOutput};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Instant;
 use uuid::Uuid;
 
@@ -143,6 +149,25 @@ impl ApprovalContext {
                     .unwrap_or_default();
                 Some(format!("GUI: {} {} in '{}'", action, elem_str, app_name))
             }
+            (
+                _,
+                ActionDetails::HomeControl {
+                    action,
+                    target,
+                    room,
+                    security,
+                },
+            ) => {
+                let room_str = room
+                    .as_deref()
+                    .map(|r| format!(" in {}", r))
+                    .unwrap_or_default();
+                let security_str = if *security { " [security device]" } else { "" };
+                Some(format!(
+                    "HomeKit: {} '{}'{}{}",
+                    action, target, room_str, security_str
+                ))
+            }
             // Browser automation previews.
             (
                 _,
@@ -163,6 +188,20 @@ impl ApprovalContext {
             (_, ActionDetails::FileDelete { path }) => {
                 Some(format!("Will delete {}", path.display()))
             }
+            (
+                _,
+                ActionDetails::MailBulk {
+                    operation,
+                    count,
+                    mailbox,
+                },
+            ) => {
+                let dest = mailbox
+                    .as_deref()
+                    .map(|m| format!(" → {}", m))
+                    .unwrap_or_default();
+                Some(format!("Mail: {} {} message(s){}", operation, count, dest))
+            }
             _ => None,
         };
         if let Some(p) = preview {
@@ -194,6 +233,19 @@ pub enum ApprovalDecision {
     Deny,
     /// Approve this action AND all future actions with the same tool+risk level in this session.
     ApproveAllSimilar,
+    /// Nobody decided before the approval expired; the action is denied.
+    /// `abort` is set when the timeout policy stops the task.
+    TimedOut { abort: bool },
+}
+
+impl ApprovalDecision {
+    /// Whether the action may run.
+    pub fn is_approved(self) -> bool {
+        matches!(
+            self,
+            ApprovalDecision::Approve | ApprovalDecision::ApproveAllSimilar
+        )
+    }
 }
 
 /// An action that the agent wants to perform.
@@ -275,11 +327,49 @@ pub enum ActionDetails {
         /// The target element description, if any.
         element: Option<String>,
     },
+    /// A smart home command (HomeKit accessory, room, or scene).
+    HomeControl {
+        /// The command being issued (e.g. "control_accessory unlock").
+        action: String,
+        /// Accessory, scene, or category name the command targets.
+        target: String,
+        /// Room scope for grouped commands.
+        room: Option<String>,
+        /// Whether the target is a security-class device (lock, garage door, alarm).
+        security: bool,
+    },
+    /// A bulk operation applied to a set of mail messages.
+    MailBulk {
+        /// The bulk operation (mark_read, flag, move, delete).
+        operation: String,
+        /// Number of messages the operation touches.
+        count: usize,
+        /// Destination mailbox for moves.
+        mailbox: Option<String>,
+    },
     Other {
         info: String,
     },
 }
 
+/// Whether an accessory name or category describes a security-class device
+/// (locks, garage doors, alarm and security systems).
+pub fn is_security_accessory(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    words.iter().any(|w| {
+        matches!(
+            *w,
+            "lock" | "locks" | "deadbolt" | "garage" | "alarm" | "alarms" | "security"
+        )
+    }) || ["lock_mechanism", "garage_door", "security_system"]
+        .iter()
+        .any(|k| lower.contains(k))
+}
+
 /// An entry in the audit log.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditEntry {
@@ -318,6 +408,66 @@ pub enum AuditEvent {
         tool: String,
         approved: bool,
     },
+    /// A declarative tool contract blocked a call or withheld its output.
+    ContractViolation {
+        tool: String,
+        contract: String,
+        condition: String,
+        detail: String,
+    },
+    /// Paranoid mode blocked a capability a call needed.
+    CapabilityBlocked {
+        tool: String,
+        capability: String,
+        target: String,
+        reason: String,
+    },
+    /// The user granted a capability for the rest of the task.
+    CapabilityGranted {
+        tool: String,
+        capability: String,
+        target: String,
+    },
+    /// A gateway device-pairing step: `offered`, `paired`, `rejected` or
+    /// `revoked`. `device` is the device name, or the challenge ID before a
+    /// device has identified itself.
+    DevicePairing {
+        action: String,
+        device: String,
+        detail: String,
+    },
+    /// A spawned agent called a tool outside its allowlist or exceeded a
+    /// resource limit. `tool` is empty for token and runtime limits.
+    AgentViolation {
+        agent_id: Uuid,
+        tool: String,
+        limit: String,
+        detail: String,
+    },
+    /// An operator closed a gateway client's connection. `client` is the
+    /// name it declared, or its address.
+    ClientDisconnected {
+        connection_id: Uuid,
+        client: String,
+        detail: String,
+    },
+    /// An approval expired without a decision. `policy` is the
+    /// `[safety.approval_timeouts]` entry that resolved it and `resolution`
+    /// what it did, e.g. "denied, task aborted".
+    ApprovalExpired {
+        tool: String,
+        policy: String,
+        resolution: String,
+        escalated: bool,
+    },
+    /// A client submitted a canvas form. `values` is the submission as JSON
+    /// with sensitive fields redacted.
+    FormSubmitted {
+        form_id: Uuid,
+        title: String,
+        client: String,
+        values: String,
+    },
 }
 
 // ---------------------------------------------------------------------------
@@ -429,12 +579,19 @@ pub enum ContractCheckResult {
     PreConditionViolation { tool: String, condition: String },
     /// Resource bound was exceeded.
     ResourceBoundExceeded { bound: String },
+    /// A declarative tool contract failed.
+    ToolContractViolation {
+        tool: String,
+        violation: ContractViolation,
+    },
 }
 
 /// Runtime contract enforcer that tracks state and validates tool calls.
 #[derive(Debug, Clone)]
 pub struct ContractEnforcer {
     contract: Option<SafetyContract>,
+    /// Declarative per-tool contracts from config and `.rustant/contracts.yaml`.
+    tool_contracts: Arc<ContractSet>,
     total_tool_calls: usize,
     destructive_calls: usize,
     total_cost: f64,
@@ -446,6 +603,7 @@ impl ContractEnforcer {
     pub fn new(contract: Option<SafetyContract>) -> Self {
         Self {
             contract,
+            tool_contracts: Arc::new(ContractSet::default()),
             total_tool_calls: 0,
             destructive_calls: 0,
             total_cost: 0.0,
@@ -461,6 +619,43 @@ impl ContractEnforcer {
         tool_name: &str,
         risk_level: RiskLevel,
         arguments: &serde_json::Value,
+    ) -> ContractCheckResult {
+        let result = self.check_safety_contract(tool_name, risk_level, arguments);
+        if result != ContractCheckResult::Satisfied {
+            return result;
+        }
+        if let Err(violation) = self.tool_contracts.check_pre(tool_name, arguments) {
+            let result = ContractCheckResult::ToolContractViolation {
+                tool: tool_name.to_string(),
+                violation,
+            };
+            self.violations.push(result.clone());
+            return result;
+        }
+        ContractCheckResult::Satisfied
+    }
+
+    /// Check declarative postconditions AFTER a tool execution.
+    pub fn check_post(&mut self, tool_name: &str, output: &ToolOutput) -> ContractCheckResult {
+        match self.tool_contracts.check_post(tool_name, output) {
+            Ok(()) => ContractCheckResult::Satisfied,
+            Err(violation) => {
+                let result = ContractCheckResult::ToolContractViolation {
+                    tool: tool_name.to_string(),
+                    violation,
+                };
+                self.violations.push(result.clone());
+                result
+            }
+        }
+    }
+
+    /// Resource bounds, invariants, and pre-conditions of the `SafetyContract`.
+    fn check_safety_contract(
+        &mut self,
+        tool_name: &str,
+        risk_level: RiskLevel,
+        arguments: &serde_json::Value,
     ) -> ContractCheckResult {
         let contract = match &self.contract {
             Some(c) => c,
@@ -566,6 +761,16 @@ impl ContractEnforcer {
         self.contract.as_ref()
     }
 
+    /// Replace the declarative tool contracts.
+    pub fn set_tool_contracts(&mut self, contracts: ContractSet) {
+        self.tool_contracts = Arc::new(contracts);
+    }
+
+    /// The declarative tool contracts, shared with sub-task runners.
+    pub fn tool_contracts(&self) -> &Arc<ContractSet> {
+        &self.tool_contracts
+    }
+
     /// Get total tool calls tracked.
     pub fn total_tool_calls(&self) -> usize {
         self.total_tool_calls
@@ -705,7 +910,8 @@ impl BehavioralFingerprint {
 }
 
 /// Adaptive trust engine that adjusts permission requirements based on
-/// session behavior. Integrates with `SafetyGuardian::check_permission`.
+/// session behavior and per-(tool, workspace) trust scores. Integrates with
+/// `SafetyGuardian::check_permission`.
 #[derive(Debug, Clone)]
 pub struct AdaptiveTrust {
     /// Minimum approvals before a tool can be auto-promoted.
@@ -716,8 +922,19 @@ pub struct AdaptiveTrust {
     pub enabled: bool,
     /// The behavioral fingerprint for this session.
     pub fingerprint: BehavioralFingerprint,
+    /// How trust scores move, decay and cap out.
+    pub policy: TrustPolicy,
+    /// Trust scores per (tool, workspace), persisted across sessions.
+    pub ledger: TrustLedger,
+    /// Workspace that scores are recorded against.
+    workspace: String,
+    /// Where the ledger is saved after each change, if anywhere.
+    store_path: Option<PathBuf>,
 }
 
+/// Workspace key used before a workspace has been set.
+const NO_WORKSPACE: &str = "(no workspace)";
+
 impl AdaptiveTrust {
     pub fn new(config: Option<&crate::config::AdaptiveTrustConfig>) -> Self {
         match config {
@@ -726,30 +943,101 @@ impl AdaptiveTrust {
                 anomaly_threshold: cfg.anomaly_threshold,
                 enabled: true,
                 fingerprint: BehavioralFingerprint::new(),
+                policy: TrustPolicy::from_config(cfg),
+                ledger: TrustLedger::new(),
+                workspace: NO_WORKSPACE.to_string(),
+                store_path: None,
             },
             _ => Self {
                 trust_escalation_threshold: 5,
                 anomaly_threshold: 0.7,
                 enabled: false,
                 fingerprint: BehavioralFingerprint::new(),
+                policy: TrustPolicy::default(),
+                ledger: TrustLedger::new(),
+                workspace: NO_WORKSPACE.to_string(),
+                store_path: None,
             },
         }
     }
 
+    /// Record future trust events against this workspace.
+    pub fn set_workspace(&mut self, workspace: &Path) {
+        self.workspace = workspace.display().to_string();
+    }
+
+    /// The workspace scores are currently recorded against.
+    pub fn workspace(&self) -> &str {
+        &self.workspace
+    }
+
+    /// Load the ledger from `path` and save it back there after every change.
+    pub fn load_store(&mut self, path: &Path) -> Result<(), TrustError> {
+        self.ledger = TrustLedger::load(path)?;
+        self.store_path = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Current trust score for a tool in this workspace.
+    pub fn score(&self, tool_name: &str) -> f64 {
+        self.ledger
+            .score(tool_name, &self.workspace, &self.policy, Utc::now())
+    }
+
+    /// Record a user approval decision.
+    pub fn record_approval(&mut self, tool_name: &str, approved: bool) {
+        self.fingerprint.record_approval(tool_name, approved);
+        let kind = if approved {
+            TrustEventKind::Approved
+        } else {
+            TrustEventKind::Denied
+        };
+        self.record_event(tool_name, kind);
+    }
+
+    /// Record a tool execution outcome.
+    pub fn record_outcome(&mut self, tool_name: &str, risk_level: RiskLevel, success: bool) {
+        self.fingerprint.record_call(tool_name, risk_level, success);
+        if !self.enabled {
+            return;
+        }
+        if success {
+            self.ledger
+                .record_use(tool_name, &self.workspace, &self.policy, Utc::now());
+            self.persist();
+        } else {
+            self.record_event(tool_name, TrustEventKind::Failed);
+        }
+    }
+
+    /// Record that the safety guardian blocked a call to this tool.
+    pub fn record_blocked(&mut self, tool_name: &str) {
+        self.record_event(tool_name, TrustEventKind::Blocked);
+    }
+
+    /// Forget trust matching `scope`, returning how many entries were removed.
+    pub fn reset(&mut self, scope: &TrustScope) -> usize {
+        let removed = self.ledger.reset(scope);
+        if removed > 0 {
+            self.persist();
+        }
+        removed
+    }
+
     /// Check if adaptive trust should auto-approve a tool (trust escalation).
     ///
-    /// Returns `true` if the tool has been approved enough times to skip
-    /// future approval prompts for this session.
-    pub fn should_auto_approve(&self, tool_name: &str) -> bool {
-        if !self.enabled {
+    /// Returns `true` if the tool's score in this workspace has reached the
+    /// auto-approve threshold and the action is within the risk ceiling.
+    /// Execute, network and destructive actions are never auto-approved.
+    pub fn should_auto_approve(&self, tool_name: &str, risk_level: RiskLevel) -> bool {
+        if !self.enabled || !self.policy.within_ceiling(risk_level) {
             return false;
         }
         // Don't escalate if anomaly is high
         if self.fingerprint.anomaly_score() > self.anomaly_threshold {
             return false;
         }
-        self.fingerprint
-            .is_trusted_tool(tool_name, self.trust_escalation_threshold)
+        self.score(tool_name) + 1e-9 >= self.policy.auto_approve_score
     }
 
     /// Check if adaptive trust should force an approval prompt (de-escalation).
@@ -762,6 +1050,23 @@ impl AdaptiveTrust {
         }
         self.fingerprint.anomaly_score() > self.anomaly_threshold
     }
+
+    fn record_event(&mut self, tool_name: &str, kind: TrustEventKind) {
+        if !self.enabled {
+            return;
+        }
+        self.ledger
+            .record(tool_name, &self.workspace, kind, &self.policy, Utc::now());
+        self.persist();
+    }
+
+    fn persist(&self) {
+        if let Some(path) = &self.store_path
+            && let Err(e) = self.ledger.save(path)
+        {
+            tracing::warn!("Failed to save trust scores: {}", e);
+        }
+    }
 }
 
 /// Rate limiter for tool calls using a sliding window.
@@ -836,6 +1141,10 @@ pub struct SafetyGuardian {
     contract_enforcer: ContractEnforcer,
     /// Rate limiter for tool calls.
     rate_limiter: ToolRateLimiter,
+    /// Paranoid-mode capability limits and the current task's grants.
+    capabilities: CapabilityPolicy,
+    /// Open incident that puts every destructive action behind approval.
+    incident_guard: Option<String>,
 }
 
 impl SafetyGuardian {
@@ -850,6 +1159,7 @@ impl SafetyGuardian {
         let adaptive_trust = AdaptiveTrust::new(config.adaptive_trust.as_ref());
         let contract_enforcer = ContractEnforcer::new(None);
         let rate_limiter = ToolRateLimiter::new(config.max_tool_calls_per_minute);
+        let capabilities = CapabilityPolicy::new(config.paranoid.clone());
         Self {
             config,
             session_id: Uuid::new_v4(),
@@ -860,6 +1170,8 @@ impl SafetyGuardian {
             adaptive_trust,
             contract_enforcer,
             rate_limiter,
+            capabilities,
+            incident_guard: None,
         }
     }
 
@@ -871,6 +1183,7 @@ impl SafetyGuardian {
                 tool: action.tool_name.clone(),
                 reason: reason.clone(),
             });
+            self.adaptive_trust.record_blocked(&action.tool_name);
             return PermissionResult::Denied { reason };
         }
 
@@ -899,6 +1212,7 @@ impl SafetyGuardian {
                             tool: action.tool_name.clone(),
                             reason: reason.clone(),
                         });
+                        self.adaptive_trust.record_blocked(&action.tool_name);
                         return PermissionResult::Denied { reason };
                     }
                     // Medium/Low severity: require human approval
@@ -915,6 +1229,62 @@ impl SafetyGuardian {
             }
         }
 
+        // Layer 1.8: Security-class home devices (locks, garage doors, alarms)
+        // always require approval, regardless of approval mode, allowlists,
+        // or adaptive trust.
+        if let ActionDetails::HomeControl {
+            action: command,
+            target,
+            security: true,
+            ..
+        } = &action.details
+        {
+            let context = format!(
+                "HomeKit security device '{}': {} — locks, garage doors, and alarms always require approval",
+                target, command
+            );
+            self.log_event(AuditEvent::ApprovalRequested {
+                tool: action.tool_name.clone(),
+                context: context.clone(),
+            });
+            return PermissionResult::RequiresApproval { context };
+        }
+
+        // Layer 1.8b: Large bulk mail deletes always require approval,
+        // regardless of approval mode, allowlists, or adaptive trust.
+        if let ActionDetails::MailBulk {
+            operation, count, ..
+        } = &action.details
+            && operation == "delete"
+            && *count > self.config.mail_bulk_delete_approval_threshold
+        {
+            let context = format!(
+                "Bulk mail delete of {} messages — deletes above {} always require approval",
+                count, self.config.mail_bulk_delete_approval_threshold
+            );
+            self.log_event(AuditEvent::ApprovalRequested {
+                tool: action.tool_name.clone(),
+                context: context.clone(),
+            });
+            return PermissionResult::RequiresApproval { context };
+        }
+
+        // Layer 1.85: While an incident is open, destructive actions always
+        // require approval, even if "approve all similar" was chosen earlier.
+        if let Some(incident) = &self.incident_guard
+            && action.risk_level >= RiskLevel::Destructive
+        {
+            let context = format!(
+                "{} (risk: {}) — incident {} is open; destructive actions require approval",
+                action.description, action.risk_level, incident
+            );
+            self.log_event(AuditEvent::ApprovalRequested {
+                tool: action.tool_name.clone(),
+                context: context.clone(),
+            });
+            return PermissionResult::RequiresApproval { context };
+        }
+
         // Layer 1.9: Check session-scoped allowlist ("approve all similar")
         if self
             .session_allowlist
@@ -944,7 +1314,10 @@ impl SafetyGuardian {
 
         // Layer 1.96: Adaptive trust — escalation
         // If tool has been repeatedly approved with no issues, auto-approve
-        if self.adaptive_trust.should_auto_approve(&action.tool_name) {
+        if self
+            .adaptive_trust
+            .should_auto_approve(&action.tool_name, action.risk_level)
+        {
             self.log_event(AuditEvent::ActionApproved {
                 tool: action.tool_name.clone(),
             });
@@ -976,6 +1349,7 @@ impl SafetyGuardian {
                     tool: action.tool_name.clone(),
                     reason: reason.clone(),
                 });
+                self.adaptive_trust.record_blocked(&action.tool_name);
             }
             PermissionResult::RequiresApproval { context } => {
                 self.log_event(AuditEvent::ApprovalRequested {
@@ -1202,7 +1576,7 @@ impl SafetyGuardian {
 
     /// Simple glob matching for path patterns.
     /// Supports: `**`, `**/suffix`, `prefix/**`, `**/*.ext`, `**/dir/**`, `*.ext`, `prefix*`
-    fn glob_matches(pattern: &str, path: &str) -> bool {
+    pub(crate) fn glob_matches(pattern: &str, path: &str) -> bool {
         if pattern == "**" {
             return true;
         }
@@ -1250,6 +1624,7 @@ impl SafetyGuardian {
 
     /// Record an event in the audit log.
     fn log_event(&mut self, event: AuditEvent) {
+        crate::event_store::record_audit(self.session_id, &event);
         let entry = AuditEntry {
             id: Uuid::new_v4(),
             timestamp: Utc::now(),
@@ -1274,8 +1649,27 @@ impl SafetyGuardian {
     /// Record a tool execution outcome in the behavioral fingerprint.
     pub fn record_behavioral_outcome(&mut self, tool: &str, risk_level: RiskLevel, success: bool) {
         self.adaptive_trust
-            .fingerprint
-            .record_call(tool, risk_level, success);
+            .record_outcome(tool, risk_level, success);
+    }
+
+    /// Record a declarative contract violation.
+    pub fn log_contract_violation(&mut self, tool: &str, violation: &ContractViolation) {
+        self.log_event(AuditEvent::ContractViolation {
+            tool: tool.to_string(),
+            contract: violation.contract.clone(),
+            condition: violation.condition(),
+            detail: violation.detail.clone(),
+        });
+    }
+
+    /// Record a spawned agent's allowlist or resource limit violation.
+    pub fn log_agent_violation(&mut self, agent_id: Uuid, tool: &str, limit: &str, detail: &str) {
+        self.log_event(AuditEvent::AgentViolation {
+            agent_id,
+            tool: tool.to_string(),
+            limit: limit.to_string(),
+            detail: detail.to_string(),
+        });
     }
 
     /// Record a user approval decision.
@@ -1284,10 +1678,8 @@ impl SafetyGuardian {
             tool: tool.to_string(),
             approved,
         });
-        // Feed into behavioral fingerprint
-        self.adaptive_trust
-            .fingerprint
-            .record_approval(tool, approved);
+        // Feed into behavioral fingerprint and trust scores
+        self.adaptive_trust.record_approval(tool, approved);
     }
 
     /// Get the audit log entries.
@@ -1350,9 +1742,100 @@ impl SafetyGuardian {
         &self.adaptive_trust.fingerprint
     }
 
+    /// Record trust scores against this workspace.
+    pub fn set_trust_workspace(&mut self, workspace: &Path) {
+        self.adaptive_trust.set_workspace(workspace);
+    }
+
+    /// Load persisted trust scores from `path` and keep them saved there.
+    pub fn load_trust_store(&mut self, path: &Path) -> Result<(), TrustError> {
+        self.adaptive_trust.load_store(path)
+    }
+
+    /// Forget trust scores matching `scope`, returning how many were removed.
+    pub fn reset_trust(&mut self, scope: &TrustScope) -> usize {
+        self.adaptive_trust.reset(scope)
+    }
+
+    /// Check a call against the paranoid-mode capability limits.
+    ///
+    /// Always [`CapabilityCheck::Allowed`] outside paranoid mode. Blocked
+    /// calls are audited.
+    pub fn check_capabilities(
+        &mut self,
+        action: &ActionRequest,
+        arguments: &serde_json::Value,
+    ) -> CapabilityCheck {
+        if self.config.approval_mode != ApprovalMode::Paranoid {
+            return CapabilityCheck::Allowed;
+        }
+        let required = crate::capabilities::required_capabilities(
+            &action.tool_name,
+            action.risk_level,
+            &action.details,
+            arguments,
+        );
+        let result = self.capabilities.check(&required);
+        if let CapabilityCheck::Blocked(denial) = &result {
+            self.log_capability_blocked(&action.tool_name, denial);
+        }
+        result
+    }
+
+    /// Record a capability blocked in paranoid mode.
+    pub fn log_capability_blocked(&mut self, tool: &str, denial: &CapabilityDenial) {
+        self.log_event(AuditEvent::CapabilityBlocked {
+            tool: tool.to_string(),
+            capability: denial.capability.kind().to_string(),
+            target: denial.capability.target().to_string(),
+            reason: denial.reason.clone(),
+        });
+        self.adaptive_trust.record_blocked(tool);
+    }
+
+    /// Grant `capabilities` to the current task and audit the grants.
+    pub fn grant_capabilities(&mut self, tool: &str, capabilities: Vec<Capability>) {
+        for capability in capabilities {
+            self.log_event(AuditEvent::CapabilityGranted {
+                tool: tool.to_string(),
+                capability: capability.kind().to_string(),
+                target: capability.target().to_string(),
+            });
+            self.capabilities.grant(tool, capability);
+        }
+    }
+
+    /// Capability grants held by the current task.
+    pub fn capability_grants(&self) -> &[CapabilityGrant] {
+        self.capabilities.grants()
+    }
+
+    /// Start a task with only the configured capability grants.
+    pub fn reset_capability_grants(&mut self) {
+        self.capabilities.begin_task();
+    }
+
+    /// Require approval for every destructive action while `incident` is
+    /// open; `None` lifts the guard.
+    pub fn set_incident_guard(&mut self, incident: Option<String>) {
+        self.incident_guard = incident;
+    }
+
+    /// The incident currently guarding destructive actions.
+    pub fn incident_guard(&self) -> Option<&str> {
+        self.incident_guard.as_deref()
+    }
+
+    /// Resolve capability paths against `workspace` and allow its project's commands.
+    pub fn set_capability_workspace(&mut self, workspace: &Path) {
+        self.capabilities.set_workspace(workspace);
+    }
+
     /// Set an active safety contract for this session.
     pub fn set_contract(&mut self, contract: SafetyContract) {
+        let tool_contracts = Arc::clone(&self.contract_enforcer.tool_contracts);
         self.contract_enforcer = ContractEnforcer::new(Some(contract));
+        self.contract_enforcer.tool_contracts = tool_contracts;
     }
 
     /// Get the contract enforcer (for pre/post checks and diagnostics).
@@ -1567,6 +2050,135 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_home_security_device_requires_approval_in_yolo() {
+        let config = SafetyConfig {
+            approval_mode: ApprovalMode::Yolo,
+            ..SafetyConfig::default()
+        };
+        let mut guardian = SafetyGuardian::new(config);
+        guardian.add_session_allowlist("homekit".into(), RiskLevel::Execute);
+
+        let action = make_action(
+            "homekit",
+            RiskLevel::Execute,
+            ActionDetails::HomeControl {
+                action: "control_accessory unlock".into(),
+                target: "Front Door Lock".into(),
+                room: None,
+                security: true,
+            },
+        );
+        match guardian.check_permission(&action) {
+            PermissionResult::RequiresApproval { context } => {
+                assert!(context.contains("Front Door Lock"));
+            }
+            other => panic!("expected approval, got {:?}", other),
+        }
+
+        let light = make_action(
+            "homekit",
+            RiskLevel::Execute,
+            ActionDetails::HomeControl {
+                action: "control_accessory on".into(),
+                target: "Desk Lamp".into(),
+                room: Some("Office".into()),
+                security: false,
+            },
+        );
+        assert_eq!(guardian.check_permission(&light), PermissionResult::Allowed);
+    }
+
+    #[test]
+    fn test_large_bulk_mail_delete_requires_approval_in_yolo() {
+        let config = SafetyConfig {
+            approval_mode: ApprovalMode::Yolo,
+            mail_bulk_delete_approval_threshold: 10,
+            ..SafetyConfig::default()
+        };
+        let mut guardian = SafetyGuardian::new(config);
+        guardian.add_session_allowlist("macos_mail".into(), RiskLevel::Destructive);
+
+        let bulk = |operation: &str, count: usize| {
+            make_action(
+                "macos_mail",
+                RiskLevel::Destructive,
+                ActionDetails::MailBulk {
+                    operation: operation.into(),
+                    count,
+                    mailbox: None,
+                },
+            )
+        };
+        match guardian.check_permission(&bulk("delete", 11)) {
+            PermissionResult::RequiresApproval { context } => {
+                assert!(context.contains("11 messages"));
+            }
+            other => panic!("expected approval, got {:?}", other),
+        }
+        assert_eq!(
+            guardian.check_permission(&bulk("delete", 10)),
+            PermissionResult::Allowed
+        );
+        assert_eq!(
+            guardian.check_permission(&bulk("mark_read", 500)),
+            PermissionResult::Allowed
+        );
+    }
+
+    #[test]
+    fn test_open_incident_requires_approval_for_destructive_actions() {
+        let config = SafetyConfig {
+            approval_mode: ApprovalMode::Yolo,
+            ..SafetyConfig::default()
+        };
+        let mut guardian = SafetyGuardian::new(config);
+        guardian.add_session_allowlist("file_delete".into(), RiskLevel::Destructive);
+        let delete = make_action(
+            "file_delete",
+            RiskLevel::Destructive,
+            ActionDetails::Other {
+                info: "rm data".into(),
+            },
+        );
+        assert_eq!(
+            guardian.check_permission(&delete),
+            PermissionResult::Allowed
+        );
+
+        guardian.set_incident_guard(Some("INC-20261015-120000".into()));
+        match guardian.check_permission(&delete) {
+            PermissionResult::RequiresApproval { context } => {
+                assert!(context.contains("INC-20261015-120000"));
+            }
+            other => panic!("expected approval, got {:?}", other),
+        }
+        let read = make_action(
+            "file_read",
+            RiskLevel::ReadOnly,
+            ActionDetails::Other {
+                info: "read".into(),
+            },
+        );
+        assert_eq!(guardian.check_permission(&read), PermissionResult::Allowed);
+
+        guardian.set_incident_guard(None);
+        assert_eq!(
+            guardian.check_permission(&delete),
+            PermissionResult::Allowed
+        );
+    }
+
+    #[test]
+    fn test_is_security_accessory() {
+        assert!(is_security_accessory("Front Door Lock"));
+        assert!(is_security_accessory("Garage"));
+        assert!(is_security_accessory("lock_mechanism"));
+        assert!(is_security_accessory("Home Alarm"));
+        assert!(!is_security_accessory("Desk Lamp"));
+        assert!(!is_security_accessory("Blocks Shelf Light"));
+    }
+
     #[test]
     fn test_denied_path_always_denied() {
         let mut guardian = default_guardian();
@@ -2188,7 +2800,7 @@ mod tests {
     fn test_adaptive_trust_disabled() {
         let trust = AdaptiveTrust::new(None);
         assert!(!trust.enabled);
-        assert!(!trust.should_auto_approve("echo"));
+        assert!(!trust.should_auto_approve("echo", RiskLevel::ReadOnly));
         assert!(!trust.should_force_approval());
     }
 
@@ -2198,20 +2810,124 @@ mod tests {
             enabled: true,
             trust_escalation_threshold: 3,
             anomaly_threshold: 0.7,
+            ..Default::default()
         };
         let mut trust = AdaptiveTrust::new(Some(&config));
 
         // Not yet trusted
-        assert!(!trust.should_auto_approve("echo"));
+        assert!(!trust.should_auto_approve("echo", RiskLevel::ReadOnly));
 
         // Build trust
         for _ in 0..3 {
-            trust.fingerprint.record_approval("echo", true);
-            trust
-                .fingerprint
-                .record_call("echo", RiskLevel::ReadOnly, true);
+            trust.record_approval("echo", true);
+            trust.record_outcome("echo", RiskLevel::ReadOnly, true);
+        }
+        assert!(trust.should_auto_approve("echo", RiskLevel::ReadOnly));
+        assert!(trust.should_auto_approve("echo", RiskLevel::Write));
+    }
+
+    #[test]
+    fn test_adaptive_trust_never_auto_approves_above_ceiling() {
+        let config = crate::config::AdaptiveTrustConfig {
+            trust_escalation_threshold: 2,
+            max_auto_approve_risk: RiskLevel::Destructive,
+            ..Default::default()
+        };
+        let mut trust = AdaptiveTrust::new(Some(&config));
+        for _ in 0..50 {
+            trust.record_approval("shell_exec", true);
+            trust.record_outcome("shell_exec", RiskLevel::Execute, true);
         }
-        assert!(trust.should_auto_approve("echo"));
+        assert!((trust.score("shell_exec") - 1.0).abs() < 1e-9);
+        assert!(trust.should_auto_approve("shell_exec", RiskLevel::Write));
+        assert!(!trust.should_auto_approve("shell_exec", RiskLevel::Execute));
+        assert!(!trust.should_auto_approve("shell_exec", RiskLevel::Network));
+        assert!(!trust.should_auto_approve("shell_exec", RiskLevel::Destructive));
+    }
+
+    #[test]
+    fn test_adaptive_trust_is_per_workspace_and_denial_revokes() {
+        let config = crate::config::AdaptiveTrustConfig {
+            trust_escalation_threshold: 2,
+            ..Default::default()
+        };
+        let mut trust = AdaptiveTrust::new(Some(&config));
+        trust.set_workspace(Path::new("/a"));
+        trust.record_approval("file_write", true);
+        trust.record_approval("file_write", true);
+        assert!(trust.should_auto_approve("file_write", RiskLevel::Write));
+
+        trust.set_workspace(Path::new("/b"));
+        assert!(!trust.should_auto_approve("file_write", RiskLevel::Write));
+
+        trust.set_workspace(Path::new("/a"));
+        trust.record_approval("file_write", false);
+        assert!(!trust.should_auto_approve("file_write", RiskLevel::Write));
+    }
+
+    #[test]
+    fn test_guardian_blocks_penalize_and_trust_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join(crate::trust::TRUST_FILE);
+        let config = SafetyConfig {
+            adaptive_trust: Some(crate::config::AdaptiveTrustConfig {
+                trust_escalation_threshold: 1,
+                ..Default::default()
+            }),
+            ..SafetyConfig::default()
+        };
+        let mut guardian = SafetyGuardian::new(config.clone());
+        guardian.set_trust_workspace(Path::new("/ws"));
+        guardian.load_trust_store(&store).unwrap();
+        guardian.log_approval_decision("file_write", true);
+
+        let write = SafetyGuardian::create_action_request(
+            "file_write",
+            RiskLevel::Write,
+            "Write file",
+            ActionDetails::FileWrite {
+                path: "src/main.rs".into(),
+                size_bytes: 10,
+            },
+        );
+        assert!(matches!(
+            guardian.check_permission(&write),
+            PermissionResult::Allowed
+        ));
+
+        // A fresh guardian picks the score up from disk.
+        let mut restarted = SafetyGuardian::new(config);
+        restarted.set_trust_workspace(Path::new("/ws"));
+        restarted.load_trust_store(&store).unwrap();
+        assert!(matches!(
+            restarted.check_permission(&write),
+            PermissionResult::Allowed
+        ));
+
+        // A blocked call to the same tool revokes the trust.
+        let blocked = SafetyGuardian::create_action_request(
+            "file_write",
+            RiskLevel::Write,
+            "Write secrets",
+            ActionDetails::FileWrite {
+                path: ".env".into(),
+                size_bytes: 10,
+            },
+        );
+        assert!(matches!(
+            restarted.check_permission(&blocked),
+            PermissionResult::Denied { .. }
+        ));
+        assert!(matches!(
+            restarted.check_permission(&write),
+            PermissionResult::RequiresApproval { .. }
+        ));
+
+        assert_eq!(
+            restarted.reset_trust(&TrustScope::Workspace("/ws".into())),
+            1
+        );
+        assert!(TrustLedger::load(&store).unwrap().is_empty());
     }
 
     #[test]
@@ -2220,16 +2936,16 @@ mod tests {
             enabled: true,
             trust_escalation_threshold: 3,
             anomaly_threshold: 0.3,
+            ..Default::default()
         };
         let mut trust = AdaptiveTrust::new(Some(&config));
 
         // Build trust
         for _ in 0..3 {
-            trust.fingerprint.record_approval("echo", true);
-            trust
-                .fingerprint
-                .record_call("echo", RiskLevel::ReadOnly, true);
+            trust.record_approval("echo", true);
+            trust.record_outcome("echo", RiskLevel::ReadOnly, true);
         }
+        assert!(trust.should_auto_approve("echo", RiskLevel::ReadOnly));
 
         // Now trigger anomalous behavior (many errors + denials)
         for _ in 0..10 {
@@ -2246,7 +2962,7 @@ mod tests {
         // Should force approval now even for previously trusted tools
         assert!(trust.should_force_approval());
         // Auto-approve blocked due to anomaly
-        assert!(!trust.should_auto_approve("echo"));
+        assert!(!trust.should_auto_approve("echo", RiskLevel::ReadOnly));
     }
 
     #[test]
@@ -0,0 +1,264 @@
+//! Agent-authored cron jobs — ownership, the approved safety settings and
+//! the per-job kill switch.
+//!
+//! The `schedule_task` tool lets the agent create cron jobs. Every such job
+//! is approved by the user, whatever the approval mode, and carries a
+//! [`JobOrigin`]: the session that created it, its budget, and the
+//! [`SafetyGrant`] it was approved under. Each run uses the approved
+//! approval mode and persona. If the safety configuration has since been
+//! broadened, the job is paused until the user approves it again.
+
+use crate::config::{ApprovalMode, SafetyConfig};
+use crate::error::SchedulerError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Name of the agent's scheduling pseudo-tool.
+pub const SCHEDULE_TASK_TOOL: &str = "schedule_task";
+
+/// Iterations a run may take when the agent does not say.
+pub const DEFAULT_MAX_ITERATIONS: usize = 15;
+
+/// Cost a run may incur when the agent does not say.
+pub const DEFAULT_MAX_COST_USD: f64 = 0.50;
+
+const KILL_SWITCH_FILE: &str = "kill_switch.json";
+
+/// Who created an agent-authored job and what it may do.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobOrigin {
+    /// Session in which the agent created the job.
+    pub session_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// When the user last approved the job.
+    pub approved_at: DateTime<Utc>,
+    /// The schedule as the agent was asked for it.
+    pub request: String,
+    /// What the schedule was understood as, shown at approval.
+    pub interpretation: String,
+    pub budget: JobBudget,
+    /// Safety settings the user approved; runs use these.
+    pub grant: SafetyGrant,
+    /// Why the job is paused awaiting re-approval, if it is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paused: Option<String>,
+}
+
+/// Limits on each run of an agent-authored job.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JobBudget {
+    pub max_iterations: usize,
+    /// Cost limit per run in USD; the run halts when it would exceed it.
+    pub max_cost_usd: f64,
+}
+
+impl Default for JobBudget {
+    fn default() -> Self {
+        Self {
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            max_cost_usd: DEFAULT_MAX_COST_USD,
+        }
+    }
+}
+
+/// The safety settings an agent-authored job was approved under.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SafetyGrant {
+    pub approval_mode: ApprovalMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persona: Option<String>,
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+    #[serde(default)]
+    pub denied_paths: Vec<String>,
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+    #[serde(default)]
+    pub ask_commands: Vec<String>,
+    #[serde(default)]
+    pub denied_commands: Vec<String>,
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+}
+
+impl SafetyGrant {
+    /// The grant for a job running in `approval_mode` as `persona` under the
+    /// allow and deny lists of `safety`.
+    pub fn capture(
+        safety: &SafetyConfig,
+        approval_mode: ApprovalMode,
+        persona: Option<&str>,
+    ) -> Self {
+        Self {
+            approval_mode,
+            persona: persona.map(str::to_string),
+            allowed_paths: safety.allowed_paths.clone(),
+            denied_paths: safety.denied_paths.clone(),
+            allowed_commands: safety.allowed_commands.clone(),
+            ask_commands: safety.ask_commands.clone(),
+            denied_commands: safety.denied_commands.clone(),
+            allowed_hosts: safety.allowed_hosts.clone(),
+        }
+    }
+
+    /// The ways `current` permits more than this grant: added allow-list
+    /// entries and removed deny-list or always-ask entries. Empty when
+    /// `current` is the same or stricter.
+    pub fn broadenings(&self, current: &SafetyConfig) -> Vec<String> {
+        let mut changes = Vec::new();
+        let added = |approved: &[String], now: &[String], what: &str, changes: &mut Vec<String>| {
+            for entry in now.iter().filter(|e| !approved.contains(e)) {
+                changes.push(format!("{} now includes '{}'", what, entry));
+            }
+        };
+        let removed =
+            |approved: &[String], now: &[String], what: &str, changes: &mut Vec<String>| {
+                for entry in approved.iter().filter(|e| !now.contains(e)) {
+                    changes.push(format!("{} no longer includes '{}'", what, entry));
+                }
+            };
+        added(
+            &self.allowed_paths,
+            &current.allowed_paths,
+            "allowed_paths",
+            &mut changes,
+        );
+        added(
+            &self.allowed_commands,
+            &current.allowed_commands,
+            "allowed_commands",
+            &mut changes,
+        );
+        added(
+            &self.allowed_hosts,
+            &current.allowed_hosts,
+            "allowed_hosts",
+            &mut changes,
+        );
+        removed(
+            &self.denied_paths,
+            &current.denied_paths,
+            "denied_paths",
+            &mut changes,
+        );
+        removed(
+            &self.ask_commands,
+            &current.ask_commands,
+            "ask_commands",
+            &mut changes,
+        );
+        removed(
+            &self.denied_commands,
+            &current.denied_commands,
+            "denied_commands",
+            &mut changes,
+        );
+        changes
+    }
+}
+
+/// Jobs stopped by their kill switch, kept in
+/// `.rustant/scheduler/kill_switch.json` so any process — the REPL, the
+/// gateway or `rustant cron kill` — can stop a job another one runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KillSwitch {
+    /// Killed job names and when they were killed.
+    #[serde(default)]
+    pub jobs: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl KillSwitch {
+    pub fn path(workspace: &Path) -> PathBuf {
+        workspace
+            .join(".rustant")
+            .join("scheduler")
+            .join(KILL_SWITCH_FILE)
+    }
+
+    /// Load the workspace's kill switches; none when the file is missing or
+    /// unreadable.
+    pub fn load(workspace: &Path) -> Self {
+        std::fs::read_to_string(Self::path(workspace))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, workspace: &Path) -> Result<(), SchedulerError> {
+        let path = Self::path(workspace);
+        let persistence = |e: &dyn std::fmt::Display| SchedulerError::PersistenceError {
+            message: format!("Failed to save kill switches: {}", e),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| persistence(&e))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| persistence(&e))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(|e| persistence(&e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| persistence(&e))
+    }
+
+    pub fn is_killed(&self, job: &str) -> bool {
+        self.jobs.contains_key(job)
+    }
+
+    /// Stop `job`. Returns false if it was already stopped.
+    pub fn kill(&mut self, job: &str) -> bool {
+        self.jobs.insert(job.to_string(), Utc::now()).is_none()
+    }
+
+    /// Let `job` run again. Returns false if it was not stopped.
+    pub fn release(&mut self, job: &str) -> bool {
+        self.jobs.remove(job).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_broadenings() {
+        let safety = SafetyConfig {
+            denied_commands: vec!["rm -rf".into()],
+            ..Default::default()
+        };
+        let grant = SafetyGrant::capture(&safety, ApprovalMode::Safe, Some("devops"));
+        assert!(grant.broadenings(&safety).is_empty());
+
+        // Stricter settings are fine.
+        let mut stricter = safety.clone();
+        stricter.allowed_hosts.clear();
+        stricter.denied_paths.push("secrets/**".into());
+        assert!(grant.broadenings(&stricter).is_empty());
+
+        let mut broader = safety.clone();
+        broader.allowed_commands.push("curl".into());
+        broader.denied_commands.clear();
+        assert_eq!(
+            grant.broadenings(&broader),
+            vec![
+                "allowed_commands now includes 'curl'".to_string(),
+                "denied_commands no longer includes 'rm -rf'".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kill_switch_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut switch = KillSwitch::load(dir.path());
+        assert!(!switch.is_killed("digest"));
+        assert!(switch.kill("digest"));
+        assert!(!switch.kill("digest"));
+        switch.save(dir.path()).unwrap();
+
+        let mut loaded = KillSwitch::load(dir.path());
+        assert!(loaded.is_killed("digest"));
+        assert!(loaded.release("digest"));
+        assert!(!loaded.release("digest"));
+    }
+}
@@ -4,9 +4,36 @@ use chrono::{DateTime, Utc};
 use cron::Schedule;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use crate::error::SchedulerError;
+use crate::scheduler::history::JobNotify;
+
+/// What happens when a job comes due while an earlier run is still going.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlapPolicy {
+    /// Drop the new run.
+    #[default]
+    Skip,
+    /// Run once more after the current run finishes; further runs are dropped.
+    QueueOne,
+    /// Run alongside earlier runs, up to this many at once.
+    Concurrent(usize),
+}
+
+/// Outcome of [`CronScheduler::begin_run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunDecision {
+    /// Start the run now.
+    Start,
+    /// An earlier run is in flight; this one starts when it finishes.
+    Queued,
+    /// Dropped under the job's overlap policy.
+    Skipped,
+}
 
 /// Configuration for a single cron job.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +49,28 @@ pub struct CronJobConfig {
     pub task: String,
     /// Whether the job is enabled.
     pub enabled: bool,
+    /// Workspace the job runs in; untagged jobs run in every workspace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<PathBuf>,
+    /// What to do when the job comes due while a run is still in flight.
+    #[serde(default)]
+    pub overlap: OverlapPolicy,
+    /// Delay each run by up to this many seconds, so jobs sharing a
+    /// schedule don't all start at once.
+    #[serde(default)]
+    pub jitter_secs: u64,
+    /// Cancel a run that takes longer than this and record it as timed out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Channel told about failed and timed-out runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify_on_failure: Option<JobNotify>,
+    /// Keep running during focus blocks instead of waiting for them to end.
+    #[serde(default)]
+    pub critical: bool,
+    /// Who created the job, when it was created by the agent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<crate::scheduler::authored::JobOrigin>,
 }
 
 impl CronJobConfig {
@@ -36,6 +85,21 @@ impl CronJobConfig {
             timezone: None,
             task: task.into(),
             enabled: true,
+            workspace: None,
+            overlap: OverlapPolicy::Skip,
+            jitter_secs: 0,
+            timeout_secs: None,
+            notify_on_failure: None,
+            critical: false,
+            origin: None,
+        }
+    }
+
+    /// Whether the job may run in `workspace`.
+    pub fn runs_in(&self, workspace: Option<&Path>) -> bool {
+        match &self.workspace {
+            Some(tagged) => workspace == Some(tagged.as_path()),
+            None => true,
         }
     }
 }
@@ -53,6 +117,12 @@ pub struct CronJob {
     pub next_run: Option<DateTime<Utc>>,
     /// How many times this job has been executed.
     pub run_count: usize,
+    /// Runs started and not yet finished.
+    #[serde(skip)]
+    pub running: usize,
+    /// Whether a run is waiting for the current one to finish.
+    #[serde(skip)]
+    pub queued: bool,
 }
 
 impl CronJob {
@@ -66,6 +136,8 @@ impl CronJob {
             last_run: None,
             next_run: None,
             run_count: 0,
+            running: 0,
+            queued: false,
         };
         job.calculate_next_run();
         Ok(job)
@@ -73,12 +145,43 @@ impl CronJob {
 
     /// Recalculate the next run time based on the cron expression.
     pub fn calculate_next_run(&mut self) {
+        let from = self.last_run.unwrap_or_else(Utc::now);
+        self.schedule_after(from);
+    }
+
+    /// Set the next run to the first slot after `from`, plus jitter.
+    fn schedule_after(&mut self, from: DateTime<Utc>) {
         if let Ok(schedule) = parse_cron_expression(&self.config.schedule) {
-            let from = self.last_run.unwrap_or_else(Utc::now);
-            self.next_run = schedule.after(&from).next();
+            // Slots are computed in the job's timezone, so "08:00" follows
+            // daylight saving time there.
+            let slot = match self
+                .config
+                .timezone
+                .as_deref()
+                .and_then(|tz| tz.parse::<chrono_tz::Tz>().ok())
+            {
+                Some(tz) => schedule
+                    .after(&from.with_timezone(&tz))
+                    .next()
+                    .map(|slot| slot.with_timezone(&Utc)),
+                None => schedule.after(&from).next(),
+            };
+            self.next_run = slot.map(|slot| slot + self.jitter_for(slot));
         }
     }
 
+    /// Start delay for the run at `slot`: spread over `0..jitter_secs` by
+    /// hashing the job name and slot, so it is stable across restarts.
+    fn jitter_for(&self, slot: DateTime<Utc>) -> chrono::Duration {
+        if self.config.jitter_secs == 0 {
+            return chrono::Duration::zero();
+        }
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.config.name.hash(&mut hasher);
+        slot.timestamp().hash(&mut hasher);
+        chrono::Duration::seconds((hasher.finish() % self.config.jitter_secs) as i64)
+    }
+
     /// Check if this job is due to run (next_run <= now).
     pub fn is_due(&self) -> bool {
         if !self.config.enabled {
@@ -103,7 +206,7 @@ impl CronJob {
 /// or 7-field (sec min hour dom month dow year) formats.
 /// 5-field expressions are automatically prefixed with "0 " (seconds=0) and suffixed
 /// with " *" (year=any) to match the cron crate's 7-field requirement.
-fn parse_cron_expression(expr: &str) -> Result<Schedule, SchedulerError> {
+pub(crate) fn parse_cron_expression(expr: &str) -> Result<Schedule, SchedulerError> {
     let fields = expr.split_whitespace().count();
     let normalized = match fields {
         5 => format!("0 {} *", expr), // Add seconds=0 prefix and year=* suffix
@@ -163,6 +266,22 @@ impl CronScheduler {
         Ok(())
     }
 
+    /// Replace the ownership record of an agent-authored job.
+    pub fn set_origin(
+        &mut self,
+        name: &str,
+        origin: crate::scheduler::authored::JobOrigin,
+    ) -> Result<(), SchedulerError> {
+        let job = self
+            .jobs
+            .get_mut(name)
+            .ok_or_else(|| SchedulerError::JobNotFound {
+                name: name.to_string(),
+            })?;
+        job.config.origin = Some(origin);
+        Ok(())
+    }
+
     /// Enable a job by name.
     pub fn enable_job(&mut self, name: &str) -> Result<(), SchedulerError> {
         let job = self
@@ -181,6 +300,72 @@ impl CronScheduler {
         self.jobs.values().filter(|j| j.is_due()).collect()
     }
 
+    /// Due jobs that may run in `workspace`; jobs tagged with another
+    /// workspace stay due until an agent in that workspace picks them up.
+    pub fn due_jobs_in(&self, workspace: Option<&Path>) -> Vec<&CronJob> {
+        self.jobs
+            .values()
+            .filter(|j| j.is_due() && j.config.runs_in(workspace))
+            .collect()
+    }
+
+    /// Claim a due job for a run, applying its overlap policy. The job's
+    /// next run is scheduled whatever the decision; call
+    /// [`finish_run`](Self::finish_run) when a started run ends.
+    pub fn begin_run(&mut self, name: &str) -> Result<RunDecision, SchedulerError> {
+        let job = self
+            .jobs
+            .get_mut(name)
+            .ok_or_else(|| SchedulerError::JobNotFound {
+                name: name.to_string(),
+            })?;
+        let decision = if job.running == 0 {
+            RunDecision::Start
+        } else {
+            match job.config.overlap {
+                OverlapPolicy::Skip => RunDecision::Skipped,
+                OverlapPolicy::QueueOne if job.queued => RunDecision::Skipped,
+                OverlapPolicy::QueueOne => {
+                    job.queued = true;
+                    RunDecision::Queued
+                }
+                OverlapPolicy::Concurrent(max) if job.running < max.max(1) => RunDecision::Start,
+                OverlapPolicy::Concurrent(_) => RunDecision::Skipped,
+            }
+        };
+        if decision == RunDecision::Start {
+            job.running += 1;
+            job.mark_executed();
+        } else {
+            job.schedule_after(Utc::now());
+        }
+        Ok(decision)
+    }
+
+    /// Record the end of a run started by [`begin_run`](Self::begin_run).
+    /// Returns true if a queued run should start now; it is already counted
+    /// as running. Under [`OverlapPolicy::Skip`], slots missed while the run
+    /// was in flight are dropped.
+    pub fn finish_run(&mut self, name: &str) -> Result<bool, SchedulerError> {
+        let job = self
+            .jobs
+            .get_mut(name)
+            .ok_or_else(|| SchedulerError::JobNotFound {
+                name: name.to_string(),
+            })?;
+        job.running = job.running.saturating_sub(1);
+        if job.queued && job.running == 0 {
+            job.queued = false;
+            job.running = 1;
+            job.mark_executed();
+            return Ok(true);
+        }
+        if job.config.overlap == OverlapPolicy::Skip && job.running == 0 && job.is_due() {
+            job.schedule_after(Utc::now());
+        }
+        Ok(false)
+    }
+
     /// Mark a job as executed.
     pub fn mark_executed(&mut self, name: &str) -> Result<(), SchedulerError> {
         let job = self
@@ -335,6 +520,32 @@ mod tests {
         assert!(!due.is_empty());
     }
 
+    #[test]
+    fn test_cron_scheduler_due_jobs_in_workspace() {
+        let mut scheduler = CronScheduler::new();
+        let mut acme = CronJobConfig::new("acme", "* * * * * * *", "acme task");
+        acme.workspace = Some(PathBuf::from("/work/acme"));
+        scheduler.add_job(acme).unwrap();
+        scheduler
+            .add_job(CronJobConfig::new("any", "* * * * * * *", "any task"))
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+
+        let names = |jobs: Vec<&CronJob>| {
+            let mut names: Vec<String> = jobs.iter().map(|j| j.config.name.clone()).collect();
+            names.sort();
+            names
+        };
+        assert_eq!(
+            names(scheduler.due_jobs_in(Some(Path::new("/work/acme")))),
+            vec!["acme", "any"]
+        );
+        assert_eq!(
+            names(scheduler.due_jobs_in(Some(Path::new("/work/other")))),
+            vec!["any"]
+        );
+    }
+
     #[test]
     fn test_cron_scheduler_state_serde() {
         let mut scheduler = CronScheduler::new();
@@ -351,6 +562,113 @@ mod tests {
         assert!(restored.get_job("b").is_some());
     }
 
+    fn due_job(overlap: OverlapPolicy) -> CronScheduler {
+        let mut scheduler = CronScheduler::new();
+        let mut config = CronJobConfig::new("email", "0 0 * * * * *", "summarize new email");
+        config.overlap = overlap;
+        scheduler.add_job(config).unwrap();
+        scheduler.jobs.get_mut("email").unwrap().next_run = Some(Utc::now());
+        scheduler
+    }
+
+    fn force_due(scheduler: &mut CronScheduler) {
+        scheduler.jobs.get_mut("email").unwrap().next_run = Some(Utc::now());
+    }
+
+    #[test]
+    fn test_overlap_skip_drops_runs_while_in_flight() {
+        let mut scheduler = due_job(OverlapPolicy::Skip);
+        assert_eq!(scheduler.begin_run("email").unwrap(), RunDecision::Start);
+        force_due(&mut scheduler);
+        assert_eq!(scheduler.begin_run("email").unwrap(), RunDecision::Skipped);
+        assert!(!scheduler.get_job("email").unwrap().is_due());
+
+        // A slot that passed during the run is dropped when it finishes.
+        force_due(&mut scheduler);
+        assert!(!scheduler.finish_run("email").unwrap());
+        let job = scheduler.get_job("email").unwrap();
+        assert_eq!(job.running, 0);
+        assert!(!job.is_due());
+        assert_eq!(job.run_count, 1);
+    }
+
+    #[test]
+    fn test_overlap_queue_one_runs_once_after_current() {
+        let mut scheduler = due_job(OverlapPolicy::QueueOne);
+        assert_eq!(scheduler.begin_run("email").unwrap(), RunDecision::Start);
+        force_due(&mut scheduler);
+        assert_eq!(scheduler.begin_run("email").unwrap(), RunDecision::Queued);
+        force_due(&mut scheduler);
+        assert_eq!(scheduler.begin_run("email").unwrap(), RunDecision::Skipped);
+
+        assert!(scheduler.finish_run("email").unwrap());
+        assert_eq!(scheduler.get_job("email").unwrap().running, 1);
+        assert!(!scheduler.finish_run("email").unwrap());
+        assert_eq!(scheduler.get_job("email").unwrap().run_count, 2);
+    }
+
+    #[test]
+    fn test_overlap_concurrent_is_capped() {
+        let mut scheduler = due_job(OverlapPolicy::Concurrent(2));
+        assert_eq!(scheduler.begin_run("email").unwrap(), RunDecision::Start);
+        force_due(&mut scheduler);
+        assert_eq!(scheduler.begin_run("email").unwrap(), RunDecision::Start);
+        force_due(&mut scheduler);
+        assert_eq!(scheduler.begin_run("email").unwrap(), RunDecision::Skipped);
+        assert_eq!(scheduler.get_job("email").unwrap().running, 2);
+    }
+
+    #[test]
+    fn test_jitter_delays_within_bound() {
+        use chrono::Timelike;
+        let mut config = CronJobConfig::new("spread", "0 0 * * * * *", "task");
+        config.jitter_secs = 300;
+        let job = CronJob::new(config).unwrap();
+        let next = job.next_run.unwrap();
+        let delay = (next.minute() * 60 + next.second()) as i64;
+        assert!(delay < 300);
+        let slot = next - chrono::Duration::seconds(delay);
+        assert_eq!(job.jitter_for(slot).num_seconds(), delay);
+    }
+
+    #[test]
+    fn test_next_run_in_job_timezone() {
+        use chrono::TimeZone;
+        let mut config = CronJobConfig::new("standup", "0 0 8 * * MON-FRI *", "task");
+        config.timezone = Some("America/New_York".into());
+        let mut job = CronJob::new(config).unwrap();
+        // Friday 2025-01-10 12:00 UTC is 07:00 in New York (EST, UTC-5).
+        job.schedule_after(Utc.with_ymd_and_hms(2025, 1, 10, 12, 0, 0).unwrap());
+        assert_eq!(
+            job.next_run,
+            Some(Utc.with_ymd_and_hms(2025, 1, 10, 13, 0, 0).unwrap())
+        );
+        // In July (EDT, UTC-4) the same local time is 12:00 UTC.
+        job.schedule_after(Utc.with_ymd_and_hms(2025, 7, 11, 11, 0, 0).unwrap());
+        assert_eq!(
+            job.next_run,
+            Some(Utc.with_ymd_and_hms(2025, 7, 11, 12, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_overlap_policy_config() {
+        let config: CronJobConfig = toml::from_str(
+            "name = \"a\"\nschedule = \"0 0 * * * * *\"\ntask = \"t\"\nenabled = true\n\
+             overlap = { concurrent = 3 }\ntimeout_secs = 600\n",
+        )
+        .unwrap();
+        assert_eq!(config.overlap, OverlapPolicy::Concurrent(3));
+        assert_eq!(config.timeout_secs, Some(600));
+        let config: CronJobConfig = toml::from_str(
+            "name = \"a\"\nschedule = \"0 0 * * * * *\"\ntask = \"t\"\nenabled = true\n\
+             overlap = \"queue_one\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.overlap, OverlapPolicy::QueueOne);
+        assert_eq!(config.jitter_secs, 0);
+    }
+
     #[test]
     fn test_cron_job_config_serde() {
         let config = CronJobConfig::new("test", "0 0 9 * * * *", "my task");
@@ -0,0 +1,312 @@
+//! Execution history for cron jobs — one record per run, kept for a
+//! configurable number of runs per job.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::channels::{ChannelManager, ChannelMessage, ChannelUser};
+use crate::error::SchedulerError;
+
+/// Characters of run output and error text kept in a record.
+pub const MAX_RUN_OUTPUT_CHARS: usize = 2000;
+
+/// File the history is stored in, inside the scheduler state directory.
+const HISTORY_FILE: &str = "history.json";
+
+/// Where failure notifications for a job are sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobNotify {
+    /// Channel name as registered in `[channels]` (e.g. "slack").
+    pub channel: String,
+    /// Conversation/chat ID on that channel.
+    #[serde(default)]
+    pub destination_id: String,
+}
+
+/// How a run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunOutcome {
+    Succeeded,
+    Failed,
+    TimedOut,
+    Cancelled,
+    /// Not started because an earlier run was still in flight.
+    Skipped,
+}
+
+impl std::fmt::Display for RunOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunOutcome::Succeeded => write!(f, "succeeded"),
+            RunOutcome::Failed => write!(f, "failed"),
+            RunOutcome::TimedOut => write!(f, "timed out"),
+            RunOutcome::Cancelled => write!(f, "cancelled"),
+            RunOutcome::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+/// One run of a cron job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRun {
+    pub id: Uuid,
+    pub job: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub outcome: RunOutcome,
+    /// Run output, truncated to [`MAX_RUN_OUTPUT_CHARS`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl JobRun {
+    /// A run of `job` that started at `started_at` and ended now.
+    pub fn finished(
+        job: impl Into<String>,
+        started_at: DateTime<Utc>,
+        outcome: RunOutcome,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            job: job.into(),
+            started_at,
+            finished_at: Utc::now(),
+            outcome,
+            output: None,
+            error: None,
+        }
+    }
+
+    /// A run dropped by the job's overlap policy.
+    pub fn skipped(job: impl Into<String>) -> Self {
+        Self::finished(job, Utc::now(), RunOutcome::Skipped)
+    }
+
+    pub fn with_output(mut self, output: &str) -> Self {
+        self.output = Some(truncate(output));
+        self
+    }
+
+    pub fn with_error(mut self, error: &str) -> Self {
+        self.error = Some(truncate(error));
+        self
+    }
+
+    /// How long the run took.
+    pub fn duration(&self) -> chrono::Duration {
+        self.finished_at - self.started_at
+    }
+
+    /// Whether the job's failure channel should hear about this run.
+    pub fn is_failure(&self) -> bool {
+        matches!(self.outcome, RunOutcome::Failed | RunOutcome::TimedOut)
+    }
+
+    /// Queue a failure notice on `manager`; the caller flushes it.
+    pub fn notify(
+        &self,
+        notify: &JobNotify,
+        manager: &mut ChannelManager,
+    ) -> Result<(), SchedulerError> {
+        let channel_type =
+            manager
+                .channel_type(&notify.channel)
+                .ok_or_else(|| SchedulerError::Notification {
+                    message: format!("channel '{}' is not registered", notify.channel),
+                })?;
+        let mut text = format!(
+            "Scheduled job '{}' {} after {}s (started {}).",
+            self.job,
+            self.outcome,
+            self.duration().num_seconds(),
+            self.started_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        if let Some(error) = &self.error {
+            text.push_str(&format!("\nError: {}", error));
+        }
+        let sender = ChannelUser::new("rustant", channel_type).with_name("Rustant");
+        manager.enqueue(
+            notify.channel.clone(),
+            ChannelMessage::text(channel_type, &notify.destination_id, sender, text)
+                .with_metadata("cron_job", self.job.clone()),
+        );
+        Ok(())
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_RUN_OUTPUT_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Runs of each job, newest last, at most `limit` per job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobHistory {
+    limit: usize,
+    runs: HashMap<String, VecDeque<JobRun>>,
+}
+
+impl JobHistory {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit: limit.max(1),
+            runs: HashMap::new(),
+        }
+    }
+
+    /// Add a run, dropping the job's oldest runs beyond the limit.
+    pub fn record(&mut self, run: JobRun) {
+        let runs = self.runs.entry(run.job.clone()).or_default();
+        runs.push_back(run);
+        while runs.len() > self.limit {
+            runs.pop_front();
+        }
+    }
+
+    /// Runs of `job`, newest first.
+    pub fn runs(&self, job: &str) -> Vec<&JobRun> {
+        self.runs
+            .get(job)
+            .map(|runs| runs.iter().rev().collect())
+            .unwrap_or_default()
+    }
+
+    /// The most recent run of `job`.
+    pub fn last(&self, job: &str) -> Option<&JobRun> {
+        self.runs.get(job).and_then(|runs| runs.back())
+    }
+
+    /// Change the per-job limit, dropping runs beyond it and the history
+    /// of jobs not in `jobs`. Returns the number of runs removed.
+    pub fn prune<'a>(&mut self, limit: usize, jobs: impl IntoIterator<Item = &'a str>) -> usize {
+        self.limit = limit.max(1);
+        let keep: Vec<&str> = jobs.into_iter().collect();
+        let mut removed = 0;
+        self.runs.retain(|job, runs| {
+            if !keep.contains(&job.as_str()) {
+                removed += runs.len();
+                return false;
+            }
+            while runs.len() > self.limit {
+                runs.pop_front();
+                removed += 1;
+            }
+            true
+        });
+        removed
+    }
+
+    /// Load the history from `state_dir`, or start empty.
+    pub fn load(state_dir: &Path, limit: usize) -> Self {
+        let mut history = std::fs::read_to_string(state_dir.join(HISTORY_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str::<JobHistory>(&json).ok())
+            .unwrap_or_else(|| Self::new(limit));
+        history.limit = limit.max(1);
+        history
+    }
+
+    /// Write the history to `state_dir`.
+    pub fn save(&self, state_dir: &Path) -> Result<(), SchedulerError> {
+        std::fs::create_dir_all(state_dir).map_err(|e| SchedulerError::PersistenceError {
+            message: format!("Failed to create state directory: {}", e),
+        })?;
+        let json =
+            serde_json::to_string_pretty(self).map_err(|e| SchedulerError::PersistenceError {
+                message: e.to_string(),
+            })?;
+        let path = state_dir.join(HISTORY_FILE);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(|e| SchedulerError::PersistenceError {
+            message: format!("Failed to write job history: {}", e),
+        })?;
+        std::fs::rename(&tmp, &path).map_err(|e| SchedulerError::PersistenceError {
+            message: format!("Failed to rename job history file: {}", e),
+        })
+    }
+}
+
+impl Default for JobHistory {
+    fn default() -> Self {
+        Self::new(20)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(job: &str, outcome: RunOutcome) -> JobRun {
+        JobRun::finished(job, Utc::now(), outcome)
+    }
+
+    #[test]
+    fn test_history_keeps_newest_runs() {
+        let mut history = JobHistory::new(2);
+        history.record(run("email", RunOutcome::Succeeded).with_output("one"));
+        history.record(run("email", RunOutcome::Failed).with_error("two"));
+        history.record(run("email", RunOutcome::TimedOut));
+        let runs = history.runs("email");
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].outcome, RunOutcome::TimedOut);
+        assert_eq!(runs[1].error.as_deref(), Some("two"));
+        assert_eq!(history.last("email").unwrap().outcome, RunOutcome::TimedOut);
+        assert!(history.runs("other").is_empty());
+    }
+
+    #[test]
+    fn test_output_is_truncated() {
+        let long = "x".repeat(MAX_RUN_OUTPUT_CHARS + 10);
+        let run = run("job", RunOutcome::Succeeded).with_output(&long);
+        assert_eq!(
+            run.output.unwrap().chars().count(),
+            MAX_RUN_OUTPUT_CHARS + 1
+        );
+    }
+
+    #[test]
+    fn test_prune_drops_removed_jobs_and_excess_runs() {
+        let mut history = JobHistory::new(5);
+        for _ in 0..4 {
+            history.record(run("a", RunOutcome::Succeeded));
+        }
+        history.record(run("gone", RunOutcome::Failed));
+        assert_eq!(history.prune(2, ["a"]), 3);
+        assert_eq!(history.runs("a").len(), 2);
+        assert!(history.runs("gone").is_empty());
+    }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = JobHistory::new(10);
+        history.record(run("email", RunOutcome::Succeeded).with_output("3 new emails"));
+        history.save(dir.path()).unwrap();
+
+        let loaded = JobHistory::load(dir.path(), 10);
+        let last = loaded.last("email").unwrap();
+        assert_eq!(last.outcome, RunOutcome::Succeeded);
+        assert_eq!(last.output.as_deref(), Some("3 new emails"));
+        assert!(
+            JobHistory::load(&dir.path().join("missing"), 10)
+                .runs("email")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_failures_are_notified() {
+        assert!(run("a", RunOutcome::Failed).is_failure());
+        assert!(run("a", RunOutcome::TimedOut).is_failure());
+        assert!(!run("a", RunOutcome::Skipped).is_failure());
+        assert!(!run("a", RunOutcome::Succeeded).is_failure());
+    }
+}
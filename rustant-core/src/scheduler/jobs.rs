@@ -3,6 +3,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use uuid::Uuid;
 
 use crate::error::SchedulerError;
@@ -43,6 +44,9 @@ pub struct BackgroundJob {
     pub result: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Workspace the job was started in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<PathBuf>,
 }
 
 impl BackgroundJob {
@@ -56,6 +60,7 @@ impl BackgroundJob {
             completed_at: None,
             result: None,
             error: None,
+            workspace: None,
         }
     }
 
@@ -111,11 +116,21 @@ impl JobManager {
 
     /// Spawn a new background job. Returns the job ID.
     pub fn spawn(&mut self, name: impl Into<String>) -> Result<Uuid, SchedulerError> {
+        self.spawn_in(name, None)
+    }
+
+    /// Spawn a background job tagged with the workspace it runs in.
+    pub fn spawn_in(
+        &mut self,
+        name: impl Into<String>,
+        workspace: Option<PathBuf>,
+    ) -> Result<Uuid, SchedulerError> {
         let active = self.active_count();
         if active >= self.max_jobs {
             return Err(SchedulerError::MaxJobsExceeded { max: self.max_jobs });
         }
         let mut job = BackgroundJob::new(name);
+        job.workspace = workspace;
         job.start();
         let id = job.id;
         self.jobs.insert(id, job);
@@ -285,6 +300,19 @@ mod tests {
         assert_eq!(job.status, JobStatus::Running);
     }
 
+    #[test]
+    fn test_job_manager_spawn_in_workspace() {
+        let mut manager = JobManager::new(10);
+        let id = manager
+            .spawn_in("index", Some(PathBuf::from("/work/acme")))
+            .unwrap();
+        let restored = JobManager::from_json(&manager.to_json().unwrap()).unwrap();
+        assert_eq!(
+            restored.get(&id).unwrap().workspace,
+            Some(PathBuf::from("/work/acme"))
+        );
+    }
+
     #[test]
     fn test_job_manager_list_jobs() {
         let mut manager = JobManager::new(10);
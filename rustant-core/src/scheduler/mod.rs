@@ -1,17 +1,25 @@
 //! Scheduling Module for Rustant.
 //!
-//! Provides cron-based scheduling, heartbeat triggers with cooldowns and quiet hours,
-//! webhook endpoints with HMAC verification, and background job management.
+//! Provides cron-based scheduling with overlap policies, jitter and run history,
+//! heartbeat triggers with cooldowns and quiet hours, webhook endpoints with HMAC
+//! verification, background job management, and cron jobs the agent schedules
+//! itself from natural-language requests.
 
+pub mod authored;
 pub mod cron;
 pub mod heartbeat;
+pub mod history;
 pub mod jobs;
+pub mod natural;
 pub mod persistence;
 pub mod webhook;
 
-pub use cron::{CronJob, CronJobConfig, CronScheduler};
+pub use authored::{JobBudget, JobOrigin, KillSwitch, SCHEDULE_TASK_TOOL, SafetyGrant};
+pub use cron::{CronJob, CronJobConfig, CronScheduler, OverlapPolicy, RunDecision};
 pub use heartbeat::{HeartbeatConfig, HeartbeatManager, HeartbeatTask, QuietHours};
+pub use history::{JobHistory, JobNotify, JobRun, RunOutcome};
 pub use jobs::{BackgroundJob, JobManager, JobStatus};
+pub use natural::{NaturalSchedule, parse_natural_schedule};
 pub use persistence::{load_state, save_state};
 pub use webhook::{
     WebhookEndpoint, WebhookHandler, WebhookRequest, WebhookResult, compute_hmac_signature,
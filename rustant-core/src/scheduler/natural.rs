@@ -0,0 +1,412 @@
+//! Natural-language schedules — "every weekday at 8am" to a cron expression.
+//!
+//! Covers the phrasings people use for recurring tasks: daily, weekdays,
+//! weekends, named days and day ranges at a time of day, a day of the month,
+//! and every N minutes or hours. Anything else is rejected with a hint rather
+//! than guessed at. Each result carries a plain description of what was
+//! understood, to be echoed back for confirmation.
+
+use crate::error::SchedulerError;
+use crate::scheduler::cron::parse_cron_expression;
+
+/// A schedule understood from natural language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NaturalSchedule {
+    /// 7-field cron expression (sec min hour dom month dow year).
+    pub expression: String,
+    /// IANA timezone the expression is evaluated in; `None` means UTC.
+    pub timezone: Option<String>,
+    /// What was understood, e.g. "every Monday–Friday at 08:00 America/New_York".
+    pub description: String,
+}
+
+const DAYS: [(&str, &str); 7] = [
+    ("MON", "Monday"),
+    ("TUE", "Tuesday"),
+    ("WED", "Wednesday"),
+    ("THU", "Thursday"),
+    ("FRI", "Friday"),
+    ("SAT", "Saturday"),
+    ("SUN", "Sunday"),
+];
+
+/// Parse `text` into a cron schedule. A timezone named in the text (an IANA
+/// name such as `America/New_York`, or `UTC`) wins over `default_timezone`.
+/// Cron expressions of 5 to 7 fields are accepted as they are.
+pub fn parse_natural_schedule(
+    text: &str,
+    default_timezone: Option<&str>,
+) -> Result<NaturalSchedule, SchedulerError> {
+    let fail = |message: &str| SchedulerError::UnrecognizedSchedule {
+        text: text.to_string(),
+        message: message.to_string(),
+    };
+
+    let mut timezone = default_timezone.map(str::to_string);
+    let mut words = Vec::new();
+    for raw in text.split(|c: char| c.is_whitespace() || c == ',') {
+        let word = raw.trim_matches(|c: char| c == '.' || c == ';');
+        if word.is_empty() {
+            continue;
+        }
+        if word.contains('/') && word.parse::<chrono_tz::Tz>().is_ok() {
+            timezone = Some(word.to_string());
+        } else if word.eq_ignore_ascii_case("utc") || word.eq_ignore_ascii_case("gmt") {
+            timezone = Some("UTC".to_string());
+        } else {
+            words.push(word.to_lowercase());
+        }
+    }
+    if let Some(tz) = &timezone
+        && tz.parse::<chrono_tz::Tz>().is_err()
+    {
+        return Err(fail(&format!("unknown timezone '{}'", tz)));
+    }
+    let zone = timezone.clone().unwrap_or_else(|| "UTC".to_string());
+
+    if looks_like_cron(&words) {
+        let expression = normalize_cron(text, &words);
+        parse_cron_expression(&expression)?;
+        return Ok(NaturalSchedule {
+            description: format!("cron `{}` {}", expression, zone),
+            expression,
+            timezone,
+        });
+    }
+
+    // Interval schedules: "every 15 minutes", "hourly", "every 2 hours".
+    if let Some(interval) = parse_interval(&words) {
+        let (expression, description) = match interval {
+            Interval::Minutes(1) => ("0 * * * * * *".to_string(), "every minute".into()),
+            Interval::Minutes(n) if (2..60).contains(&n) => (
+                format!("0 */{} * * * * *", n),
+                format!("every {} minutes", n),
+            ),
+            Interval::Hours(1) => ("0 0 * * * * *".to_string(), "every hour".into()),
+            Interval::Hours(n) if (2..24).contains(&n) => {
+                (format!("0 0 */{} * * * *", n), format!("every {} hours", n))
+            }
+            _ => return Err(fail("intervals must be 1–59 minutes or 1–23 hours")),
+        };
+        return Ok(NaturalSchedule {
+            expression,
+            timezone,
+            description,
+        });
+    }
+
+    let (hour, minute) = parse_time(&words)?
+        .ok_or_else(|| fail("add a time of day, e.g. 'every weekday at 8am'"))?;
+    let time = format!("{:02}:{:02}", hour, minute);
+
+    if let Some(day) = parse_day_of_month(&words) {
+        if !(1..=31).contains(&day) {
+            return Err(fail("day of the month must be 1–31"));
+        }
+        return Ok(NaturalSchedule {
+            expression: format!("0 {} {} {} * * *", minute, hour, day),
+            timezone,
+            description: format!("on day {} of every month at {} {}", day, time, zone),
+        });
+    }
+
+    let days = parse_days(&words);
+    let recurring = words.iter().any(|w| {
+        matches!(
+            w.as_str(),
+            "every" | "each" | "daily" | "weekdays" | "weekends" | "day" | "days"
+        )
+    });
+    let (dow, when) = match days.as_slice() {
+        [] if recurring || words.iter().any(|w| w == "at") => ("*".to_string(), "every day".into()),
+        [] => return Err(fail("say how often, e.g. 'every day at 9am'")),
+        all if all.len() == 7 => ("*".to_string(), "every day".into()),
+        [0, 1, 2, 3, 4] => ("MON-FRI".to_string(), "every Monday–Friday".into()),
+        days => (
+            days.iter()
+                .map(|&d| DAYS[d].0)
+                .collect::<Vec<_>>()
+                .join(","),
+            format!("every {}", join_names(days)),
+        ),
+    };
+    Ok(NaturalSchedule {
+        expression: format!("0 {} {} * * {} *", minute, hour, dow),
+        timezone,
+        description: format!("{} at {} {}", when, time, zone),
+    })
+}
+
+fn looks_like_cron(words: &[String]) -> bool {
+    (5..=7).contains(&words.len())
+        && words.iter().all(|w| {
+            w.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "*/,-?".contains(c))
+        })
+        && words.iter().any(|w| w.contains('*'))
+}
+
+/// The cron fields in their original case, as 7 fields.
+fn normalize_cron(text: &str, words: &[String]) -> String {
+    let fields: Vec<&str> = text
+        .split_whitespace()
+        .filter(|w| words.iter().any(|word| word.eq_ignore_ascii_case(w)))
+        .collect();
+    match fields.len() {
+        5 => format!("0 {} *", fields.join(" ")),
+        6 => format!("{} *", fields.join(" ")),
+        _ => fields.join(" "),
+    }
+}
+
+enum Interval {
+    Minutes(u32),
+    Hours(u32),
+}
+
+fn parse_interval(words: &[String]) -> Option<Interval> {
+    if words.iter().any(|w| w == "hourly") {
+        return Some(Interval::Hours(1));
+    }
+    let every = words.iter().position(|w| w == "every" || w == "each")?;
+    let (count, unit) = match words.get(every + 1)?.parse::<u32>() {
+        Ok(n) => (n, words.get(every + 2)?),
+        Err(_) => (1, &words[every + 1]),
+    };
+    match unit.as_str() {
+        "minute" | "minutes" | "min" | "mins" => Some(Interval::Minutes(count)),
+        "hour" | "hours" | "hr" | "hrs" => Some(Interval::Hours(count)),
+        _ => None,
+    }
+}
+
+/// The time of day after "at", or "noon"/"midnight" anywhere.
+fn parse_time(words: &[String]) -> Result<Option<(u32, u32)>, SchedulerError> {
+    if words.iter().any(|w| w == "noon" || w == "midday") {
+        return Ok(Some((12, 0)));
+    }
+    if words.iter().any(|w| w == "midnight") {
+        return Ok(Some((0, 0)));
+    }
+    let Some(at) = words.iter().position(|w| w == "at") else {
+        return Ok(None);
+    };
+    let Some(word) = words.get(at + 1) else {
+        return Ok(None);
+    };
+    let invalid = || SchedulerError::UnrecognizedSchedule {
+        text: words.join(" "),
+        message: format!("'{}' is not a time of day", word),
+    };
+
+    let (clock, mut meridiem) = match word.strip_suffix("am").or(word.strip_suffix("a.m")) {
+        Some(clock) => (clock, Some(false)),
+        None => match word.strip_suffix("pm").or(word.strip_suffix("p.m")) {
+            Some(clock) => (clock, Some(true)),
+            None => (word.as_str(), None),
+        },
+    };
+    if meridiem.is_none() {
+        meridiem = match words.get(at + 2).map(String::as_str) {
+            Some("am" | "a.m") => Some(false),
+            Some("pm" | "p.m") => Some(true),
+            _ => None,
+        };
+    }
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>(), m.parse::<u32>()),
+        None => (clock.parse::<u32>(), Ok(0)),
+    };
+    let (Ok(mut hour), Ok(minute)) = (hour, minute) else {
+        return Err(invalid());
+    };
+    match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return Err(invalid()),
+        Some(true) if hour != 12 => hour += 12,
+        Some(false) if hour == 12 => hour = 0,
+        _ => {}
+    }
+    if hour > 23 || minute > 59 {
+        return Err(invalid());
+    }
+    Ok(Some((hour, minute)))
+}
+
+/// "on the 1st", "the 15th of every month", "monthly" (the 1st).
+fn parse_day_of_month(words: &[String]) -> Option<u32> {
+    let monthly = words.iter().any(|w| w == "monthly" || w == "month");
+    let ordinal = words.iter().find_map(|w| {
+        let digits = w
+            .strip_suffix("st")
+            .or(w.strip_suffix("nd"))
+            .or(w.strip_suffix("rd"))
+            .or(w.strip_suffix("th"))?;
+        digits.parse::<u32>().ok()
+    });
+    match (monthly, ordinal) {
+        (_, Some(day)) => Some(day),
+        (true, None) => Some(1),
+        (false, None) => None,
+    }
+}
+
+fn day_index(word: &str) -> Option<usize> {
+    let word = word.strip_suffix('s').unwrap_or(word);
+    DAYS.iter().position(|(short, long)| {
+        let long = long.to_lowercase();
+        word == long || word == short.to_lowercase() || (word.len() >= 3 && long.starts_with(word))
+    })
+}
+
+/// Days of the week mentioned, Monday = 0, sorted. Understands "weekday(s)",
+/// "weekend(s)", single names and ranges ("monday to friday", "mon-fri").
+fn parse_days(words: &[String]) -> Vec<usize> {
+    let mut days = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i].as_str();
+        match word {
+            "weekday" | "weekdays" => days.extend(0..5),
+            "weekend" | "weekends" => days.extend(5..7),
+            _ => {
+                let range = word
+                    .split_once(['-', '–'])
+                    .and_then(|(a, b)| Some((day_index(a)?, day_index(b)?)));
+                if let Some((start, end)) = range {
+                    days.extend(day_range(start, end));
+                } else if let Some(start) = day_index(word) {
+                    let to = words.get(i + 1).map(String::as_str);
+                    if matches!(to, Some("to" | "through" | "thru" | "until" | "-"))
+                        && let Some(end) = words.get(i + 2).and_then(|w| day_index(w))
+                    {
+                        days.extend(day_range(start, end));
+                        i += 2;
+                    } else {
+                        days.push(start);
+                    }
+                }
+            }
+        }
+        i += 1;
+    }
+    days.sort_unstable();
+    days.dedup();
+    days
+}
+
+fn day_range(start: usize, end: usize) -> Vec<usize> {
+    let mut days = vec![start];
+    let mut day = start;
+    while day != end {
+        day = (day + 1) % 7;
+        days.push(day);
+    }
+    days
+}
+
+/// "Monday", "Monday and Friday", "Monday, Wednesday and Friday".
+fn join_names(days: &[usize]) -> String {
+    let names: Vec<&str> = days.iter().map(|&d| DAYS[d].1).collect();
+    match names.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> NaturalSchedule {
+        parse_natural_schedule(text, None).unwrap()
+    }
+
+    #[test]
+    fn test_weekdays_with_timezone() {
+        let schedule = parse("every weekday at 8am America/New_York");
+        assert_eq!(schedule.expression, "0 0 8 * * MON-FRI *");
+        assert_eq!(schedule.timezone.as_deref(), Some("America/New_York"));
+        assert_eq!(
+            schedule.description,
+            "every Monday–Friday at 08:00 America/New_York"
+        );
+    }
+
+    #[test]
+    fn test_days_and_times() {
+        assert_eq!(parse("daily at 18:30").expression, "0 30 18 * * * *");
+        assert_eq!(parse("every day at 9:15 pm").expression, "0 15 21 * * * *");
+        assert_eq!(parse("every day at 12am").expression, "0 0 0 * * * *");
+        assert_eq!(parse("every monday at noon").expression, "0 0 12 * * MON *");
+        let schedule = parse("mondays, wednesdays and fridays at 7pm");
+        assert_eq!(schedule.expression, "0 0 19 * * MON,WED,FRI *");
+        assert_eq!(
+            schedule.description,
+            "every Monday, Wednesday and Friday at 19:00 UTC"
+        );
+        assert_eq!(
+            parse("every weekend at 10am").expression,
+            "0 0 10 * * SAT,SUN *"
+        );
+        assert_eq!(
+            parse("monday to friday at 8am").expression,
+            "0 0 8 * * MON-FRI *"
+        );
+        assert_eq!(
+            parse("every fri-mon at 8am").expression,
+            "0 0 8 * * MON,FRI,SAT,SUN *"
+        );
+    }
+
+    #[test]
+    fn test_intervals_and_monthly() {
+        assert_eq!(parse("every 15 minutes").expression, "0 */15 * * * * *");
+        assert_eq!(parse("hourly").expression, "0 0 * * * * *");
+        assert_eq!(parse("every 2 hours").description, "every 2 hours");
+        let schedule = parse("on the 1st of every month at 9am");
+        assert_eq!(schedule.expression, "0 0 9 1 * * *");
+        assert_eq!(schedule.description, "on day 1 of every month at 09:00 UTC");
+        assert_eq!(parse("monthly at 6am").expression, "0 0 6 1 * * *");
+    }
+
+    #[test]
+    fn test_cron_expressions_pass_through() {
+        assert_eq!(parse("0 9 * * MON-FRI").expression, "0 0 9 * * MON-FRI *");
+        assert_eq!(
+            parse("0 0 9 * * MON-FRI *").expression,
+            "0 0 9 * * MON-FRI *"
+        );
+    }
+
+    #[test]
+    fn test_default_timezone() {
+        let schedule = parse_natural_schedule("every day at 7am", Some("Europe/Berlin")).unwrap();
+        assert_eq!(schedule.timezone.as_deref(), Some("Europe/Berlin"));
+        let schedule =
+            parse_natural_schedule("every day at 7am UTC", Some("Europe/Berlin")).unwrap();
+        assert_eq!(schedule.timezone.as_deref(), Some("UTC"));
+    }
+
+    #[test]
+    fn test_rejects_what_it_cannot_read() {
+        for text in [
+            "every weekday",
+            "sometimes",
+            "every day at 25:00",
+            "every day at 13pm",
+            "every 90 minutes",
+            "on the 40th at 9am",
+        ] {
+            assert!(
+                matches!(
+                    parse_natural_schedule(text, None),
+                    Err(SchedulerError::UnrecognizedSchedule { .. })
+                ),
+                "{}",
+                text
+            );
+        }
+        assert!(parse_natural_schedule("every day at 9am", Some("Mars/Olympus")).is_err());
+    }
+}
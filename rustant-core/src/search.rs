@@ -6,9 +6,17 @@
 //!
 //! This module uses a simple TF-IDF–style embedding (bag-of-words) rather
 //! than requiring an external embedding model.
-
+//!
+//! [`HybridSearchEngine::search_query`] adds quoted phrases (boosted when
+//! matched exactly), filters on per-fact [`FactMetadata`], optional score
+//! explanations, and pagination. Candidates are drawn from a fixed pool and
+//! ordered by score with the fact id as a tie-break, so asking for the next
+//! page never reshuffles results already seen.
+
+use chrono::{DateTime, Utc};
+use globset::{Glob, GlobSetBuilder};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::PathBuf;
 use tantivy::collector::TopDocs;
 use tantivy::query::QueryParser;
@@ -27,10 +35,202 @@ pub struct SearchResult {
     pub full_text_score: f32,
     pub vector_score: f32,
     pub combined_score: f32,
+    /// How the score was reached; set when explanations are requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<SearchExplanation>,
+}
+
+/// Breakdown of a result's score.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchExplanation {
+    /// Full-text (BM25) score before weighting.
+    pub lexical_score: f32,
+    /// Embedding similarity before weighting.
+    pub semantic_score: f32,
+    /// Boost added for exact phrase matches.
+    pub phrase_boost: f32,
+    /// The blended score results are ranked by.
+    pub final_score: f32,
+    /// Query terms found in the content.
+    pub matched_terms: Vec<String>,
+    /// Phrases found verbatim in the content.
+    pub matched_phrases: Vec<String>,
+    /// Content words sharing an embedding dimension with a query term, which
+    /// raise the semantic score without matching a term.
+    pub embedding_neighbors: Vec<String>,
+}
+
+impl std::fmt::Display for SearchExplanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "lexical {:.2}, semantic {:.2}",
+            self.lexical_score, self.semantic_score
+        )?;
+        if self.phrase_boost > 0.0 {
+            write!(f, ", phrase +{:.2}", self.phrase_boost)?;
+        }
+        write!(f, ", final {:.2}", self.final_score)?;
+        if !self.matched_terms.is_empty() {
+            write!(f, "; terms: {}", self.matched_terms.join(", "))?;
+        }
+        if !self.matched_phrases.is_empty() {
+            write!(f, "; phrases: \"{}\"", self.matched_phrases.join("\", \""))?;
+        }
+        if !self.embedding_neighbors.is_empty() {
+            write!(f, "; neighbors: {}", self.embedding_neighbors.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Attributes of an indexed fact that query filters test. Facts indexed
+/// without a value for an attribute never pass a filter on it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FactMetadata {
+    /// Workspace-relative path of the file the fact came from.
+    pub path: Option<String>,
+    /// Language name, e.g. `rust`.
+    pub language: Option<String>,
+    /// Symbol kind for facts describing a definition, e.g. `function`.
+    pub kind: Option<String>,
+    /// Last modification time of the source.
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// Query-time filters. Every filter given must pass.
+#[derive(Debug, Clone, Default)]
+pub struct QueryFilters {
+    /// Globs the fact's path must match one of.
+    pub paths: Vec<String>,
+    /// Language name or file extension.
+    pub language: Option<String>,
+    /// Symbol kinds the fact must have one of.
+    pub kinds: Vec<String>,
+    /// Only facts modified at or after this time.
+    pub modified_since: Option<DateTime<Utc>>,
+}
+
+impl QueryFilters {
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+            && self.language.is_none()
+            && self.kinds.is_empty()
+            && self.modified_since.is_none()
+    }
+}
+
+/// A hybrid search request with filters and paging.
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    /// Query text; `"quoted phrases"` are matched as phrases.
+    pub text: String,
+    pub filters: QueryFilters,
+    /// Results to skip, for fetching later pages.
+    pub offset: usize,
+    /// Page size; the configured `max_results` when `None`.
+    pub limit: Option<usize>,
+    /// Attach a [`SearchExplanation`] to each result.
+    pub explain: bool,
+}
+
+impl SearchQuery {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// One page of ranked results.
+#[derive(Debug, Clone, Default)]
+pub struct SearchPage {
+    pub results: Vec<SearchResult>,
+    /// Offset of the first result on this page.
+    pub offset: usize,
+    /// Matching results across all pages.
+    pub total: usize,
+    /// Offset to request for the next page, if there is one.
+    pub next_offset: Option<usize>,
+}
+
+/// A query split into loose terms and quoted phrases.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedQuery {
+    pub terms: Vec<String>,
+    pub phrases: Vec<String>,
+}
+
+/// Split `query` into terms and `"quoted phrases"`. An unmatched quote is
+/// ignored and the text after it read as terms.
+pub fn parse_query(query: &str) -> ParsedQuery {
+    let mut parsed = ParsedQuery::default();
+    let segments: Vec<&str> = query.split('"').collect();
+    let closed = segments.len() % 2 == 1;
+    for (i, segment) in segments.iter().enumerate() {
+        let quoted = i % 2 == 1 && (closed || i + 1 < segments.len());
+        if quoted {
+            let phrase = segment.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !phrase.is_empty() {
+                parsed.phrases.push(phrase);
+            }
+        } else {
+            parsed
+                .terms
+                .extend(segment.split_whitespace().map(str::to_string));
+        }
+    }
+    parsed
+}
+
+impl ParsedQuery {
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.phrases.is_empty()
+    }
+
+    /// Whether `text` contains every term and phrase, case-insensitively.
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.terms
+            .iter()
+            .chain(&self.phrases)
+            .all(|t| text.contains(&t.to_lowercase()))
+    }
+
+    /// Phrases to boost: the quoted ones, or the whole query when it has
+    /// several terms and no quotes.
+    fn boost_phrases(&self) -> Vec<String> {
+        if self.phrases.is_empty() && self.terms.len() > 1 {
+            vec![self.terms.join(" ")]
+        } else {
+            self.phrases.clone()
+        }
+    }
+
+    /// Query string for Tantivy, with phrases re-quoted and balanced.
+    fn tantivy_query(&self) -> String {
+        let mut parts = self.terms.clone();
+        parts.extend(self.phrases.iter().map(|p| format!("\"{}\"", p)));
+        parts.join(" ")
+    }
+
+    /// Plain text for embedding.
+    fn plain_text(&self) -> String {
+        let mut parts = self.terms.clone();
+        parts.extend(self.phrases.iter().cloned());
+        parts.join(" ")
+    }
+
+    /// Lowercased words of every term and phrase.
+    fn words(&self) -> BTreeSet<String> {
+        words(&self.plain_text()).into_iter().collect()
+    }
 }
 
 /// Configuration for the hybrid search engine.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SearchConfig {
     /// Directory for the Tantivy index.
     pub index_path: PathBuf,
@@ -44,6 +244,14 @@ pub struct SearchConfig {
     pub vector_weight: f32,
     /// Maximum number of results to return.
     pub max_results: usize,
+    /// Score added to results containing a quoted phrase verbatim (or the
+    /// whole query, when it has several words and no quotes).
+    pub phrase_boost: f32,
+    /// Candidates ranked per query before filtering and paging. Pages past
+    /// the pool are empty, which keeps earlier pages stable.
+    pub candidate_pool: usize,
+    /// Attach score explanations to every result.
+    pub explain: bool,
 }
 
 impl Default for SearchConfig {
@@ -55,6 +263,9 @@ impl Default for SearchConfig {
             full_text_weight: 0.5,
             vector_weight: 0.5,
             max_results: 10,
+            phrase_boost: 1.0,
+            candidate_pool: 200,
+            explain: false,
         }
     }
 }
@@ -126,6 +337,15 @@ impl SimpleEmbedder {
     }
 }
 
+/// Lowercased alphanumeric words of `text`, as the embedder sees them.
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 fn simple_hash(s: &str) -> usize {
     let mut hash: usize = 5381;
     for b in s.bytes() {
@@ -164,6 +384,9 @@ pub struct HybridSearchEngine {
     embedder: SimpleEmbedder,
     // In-memory vector store (backed by SQLite for persistence)
     vectors: HashMap<String, Vec<f32>>,
+    /// Content of facts indexed this session, for vector-only hits.
+    contents: HashMap<String, String>,
+    metadata: HashMap<String, FactMetadata>,
 }
 
 impl std::fmt::Debug for HybridSearchEngine {
@@ -215,11 +438,23 @@ impl HybridSearchEngine {
             content_field,
             embedder,
             vectors: HashMap::new(),
+            contents: HashMap::new(),
+            metadata: HashMap::new(),
         })
     }
 
     /// Index a fact for both full-text and vector search.
     pub fn index_fact(&mut self, fact_id: &str, content: &str) -> Result<(), SearchError> {
+        self.index_fact_with(fact_id, content, FactMetadata::default())
+    }
+
+    /// Index a fact with attributes for query filters.
+    pub fn index_fact_with(
+        &mut self,
+        fact_id: &str,
+        content: &str,
+        metadata: FactMetadata,
+    ) -> Result<(), SearchError> {
         // Tantivy full-text
         self.writer
             .add_document(doc!(
@@ -235,6 +470,9 @@ impl HybridSearchEngine {
         // Vector embedding
         let embedding = self.embedder.embed(content);
         self.vectors.insert(fact_id.to_string(), embedding);
+        self.contents
+            .insert(fact_id.to_string(), content.to_string());
+        self.metadata.insert(fact_id.to_string(), metadata);
 
         Ok(())
     }
@@ -248,11 +486,17 @@ impl HybridSearchEngine {
             .map_err(|e| SearchError::IndexError(format!("Failed to commit delete: {}", e)))?;
 
         self.vectors.remove(fact_id);
+        self.contents.remove(fact_id);
+        self.metadata.remove(fact_id);
         Ok(())
     }
 
     /// Full-text search only.
     pub fn search_text(&self, query: &str) -> Result<Vec<SearchResult>, SearchError> {
+        self.text_candidates(query, self.config.max_results)
+    }
+
+    fn text_candidates(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, SearchError> {
         self.reader
             .reload()
             .map_err(|e| SearchError::IndexError(format!("Failed to reload reader: {}", e)))?;
@@ -264,7 +508,7 @@ impl HybridSearchEngine {
             .map_err(|e| SearchError::IndexError(format!("Failed to parse query: {}", e)))?;
 
         let top_docs = searcher
-            .search(&parsed, &TopDocs::with_limit(self.config.max_results))
+            .search(&parsed, &TopDocs::with_limit(limit))
             .map_err(|e| SearchError::IndexError(format!("Search failed: {}", e)))?;
 
         let mut results = Vec::new();
@@ -290,6 +534,7 @@ impl HybridSearchEngine {
                 full_text_score: score,
                 vector_score: 0.0,
                 combined_score: score,
+                explanation: None,
             });
         }
 
@@ -298,6 +543,10 @@ impl HybridSearchEngine {
 
     /// Vector similarity search only.
     pub fn search_vector(&self, query: &str) -> Vec<SearchResult> {
+        self.vector_candidates(query, self.config.max_results)
+    }
+
+    fn vector_candidates(&self, query: &str, limit: usize) -> Vec<SearchResult> {
         let query_embedding = self.embedder.embed(query);
 
         let mut scored: Vec<(String, f32)> = self
@@ -309,8 +558,8 @@ impl HybridSearchEngine {
             })
             .collect();
 
-        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        scored.truncate(self.config.max_results);
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        scored.truncate(limit);
 
         scored
             .into_iter()
@@ -320,66 +569,129 @@ impl HybridSearchEngine {
                 full_text_score: 0.0,
                 vector_score: score,
                 combined_score: score,
+                explanation: None,
             })
             .collect()
     }
 
     /// Hybrid search: combines full-text and vector results with weighted scoring.
     pub fn search(&self, query: &str) -> Result<Vec<SearchResult>, SearchError> {
-        let text_results = self.search_text(query)?;
-        let vector_results = self.search_vector(query);
+        Ok(self.search_query(&SearchQuery::new(query))?.results)
+    }
+
+    /// Hybrid search with phrases, filters, explanations and paging.
+    ///
+    /// Up to `candidate_pool` full-text and vector candidates are merged,
+    /// boosted for exact phrase matches, filtered, and ranked by blended
+    /// score with the fact id breaking ties. The page is then cut from that
+    /// ranking, so successive offsets walk one fixed order.
+    pub fn search_query(&self, query: &SearchQuery) -> Result<SearchPage, SearchError> {
+        let parsed = parse_query(&query.text);
+        let limit = query.limit.unwrap_or(self.config.max_results);
+        if parsed.is_empty() || limit == 0 {
+            return Ok(SearchPage {
+                offset: query.offset,
+                ..Default::default()
+            });
+        }
+        let pool = self.config.candidate_pool.max(self.config.max_results);
+        let text_results = self.text_candidates(&parsed.tantivy_query(), pool)?;
+        let vector_results = self.vector_candidates(&parsed.plain_text(), pool);
 
         // Merge results by fact_id
         let mut merged: HashMap<String, SearchResult> = HashMap::new();
-
         for r in text_results {
-            merged
-                .entry(r.fact_id.clone())
-                .and_modify(|existing| {
-                    existing.full_text_score = r.full_text_score;
-                })
-                .or_insert(SearchResult {
-                    fact_id: r.fact_id,
-                    content: r.content,
-                    full_text_score: r.full_text_score,
-                    vector_score: 0.0,
-                    combined_score: 0.0,
-                });
+            merged.insert(r.fact_id.clone(), r);
         }
-
         for r in vector_results {
+            let score = r.vector_score;
             merged
                 .entry(r.fact_id.clone())
-                .and_modify(|existing| {
-                    existing.vector_score = r.vector_score;
-                })
-                .or_insert(SearchResult {
-                    fact_id: r.fact_id,
-                    content: r.content,
-                    full_text_score: 0.0,
-                    vector_score: r.vector_score,
-                    combined_score: 0.0,
-                });
+                .and_modify(|existing| existing.vector_score = score)
+                .or_insert(r);
         }
 
-        // Compute combined scores
+        let filter = FactFilter::new(&query.filters)?;
+        let boost_phrases = parsed.boost_phrases();
+        let explain = query.explain || self.config.explain;
         let mut results: Vec<SearchResult> = merged
             .into_values()
+            .filter(|r| r.full_text_score > 0.0 || r.vector_score > 0.0)
+            .filter(|r| filter.matches(self.metadata.get(&r.fact_id)))
             .map(|mut r| {
+                if r.content.is_empty()
+                    && let Some(content) = self.contents.get(&r.fact_id)
+                {
+                    r.content = content.clone();
+                }
+                let lowered = r.content.to_lowercase();
+                let matched_phrases: Vec<String> = boost_phrases
+                    .iter()
+                    .filter(|p| lowered.contains(&p.to_lowercase()))
+                    .cloned()
+                    .collect();
+                let boost = if boost_phrases.is_empty() {
+                    0.0
+                } else {
+                    self.config.phrase_boost * matched_phrases.len() as f32
+                        / boost_phrases.len() as f32
+                };
                 r.combined_score = r.full_text_score * self.config.full_text_weight
-                    + r.vector_score * self.config.vector_weight;
+                    + r.vector_score * self.config.vector_weight
+                    + boost;
+                if explain {
+                    r.explanation = Some(self.explain(&parsed, &r, boost, matched_phrases));
+                }
                 r
             })
             .collect();
 
         results.sort_by(|a, b| {
             b.combined_score
-                .partial_cmp(&a.combined_score)
-                .unwrap_or(std::cmp::Ordering::Equal)
+                .total_cmp(&a.combined_score)
+                .then_with(|| a.fact_id.cmp(&b.fact_id))
         });
-        results.truncate(self.config.max_results);
+        let total = results.len();
+        let results: Vec<SearchResult> =
+            results.into_iter().skip(query.offset).take(limit).collect();
+        let end = query.offset + results.len();
+        Ok(SearchPage {
+            results,
+            offset: query.offset,
+            total,
+            next_offset: (end < total).then_some(end),
+        })
+    }
 
-        Ok(results)
+    fn explain(
+        &self,
+        parsed: &ParsedQuery,
+        result: &SearchResult,
+        phrase_boost: f32,
+        matched_phrases: Vec<String>,
+    ) -> SearchExplanation {
+        let query_words = parsed.words();
+        let content_words: BTreeSet<String> = words(&result.content).into_iter().collect();
+        let buckets: Vec<usize> = query_words
+            .iter()
+            .map(|w| simple_hash(w) % self.config.vector_dimensions.max(1))
+            .collect();
+        let embedding_neighbors = content_words
+            .iter()
+            .filter(|w| !query_words.contains(*w))
+            .filter(|w| buckets.contains(&(simple_hash(w) % self.config.vector_dimensions.max(1))))
+            .take(5)
+            .cloned()
+            .collect();
+        SearchExplanation {
+            lexical_score: result.full_text_score,
+            semantic_score: result.vector_score,
+            phrase_boost,
+            final_score: result.combined_score,
+            matched_terms: query_words.intersection(&content_words).cloned().collect(),
+            matched_phrases,
+            embedding_neighbors,
+        }
     }
 
     /// Number of indexed facts.
@@ -393,6 +705,76 @@ impl HybridSearchEngine {
     }
 }
 
+/// [`QueryFilters`] compiled for matching.
+struct FactFilter<'a> {
+    filters: &'a QueryFilters,
+    paths: Option<globset::GlobSet>,
+}
+
+impl<'a> FactFilter<'a> {
+    fn new(filters: &'a QueryFilters) -> Result<Self, SearchError> {
+        let paths = if filters.paths.is_empty() {
+            None
+        } else {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in &filters.paths {
+                let glob = Glob::new(pattern).map_err(|e| {
+                    SearchError::IndexError(format!("Invalid path glob '{}': {}", pattern, e))
+                })?;
+                builder.add(glob);
+            }
+            Some(
+                builder
+                    .build()
+                    .map_err(|e| SearchError::IndexError(e.to_string()))?,
+            )
+        };
+        Ok(Self { filters, paths })
+    }
+
+    fn matches(&self, metadata: Option<&FactMetadata>) -> bool {
+        if self.filters.is_empty() {
+            return true;
+        }
+        let Some(metadata) = metadata else {
+            return false;
+        };
+        if let Some(paths) = &self.paths
+            && !metadata.path.as_ref().is_some_and(|p| paths.is_match(p))
+        {
+            return false;
+        }
+        if let Some(language) = &self.filters.language {
+            let language = language.trim_start_matches('.').to_lowercase();
+            let by_name = metadata.language.as_deref() == Some(language.as_str());
+            let by_ext = metadata
+                .path
+                .as_deref()
+                .and_then(|p| p.rsplit_once('.'))
+                .is_some_and(|(_, ext)| ext.eq_ignore_ascii_case(&language));
+            if !by_name && !by_ext {
+                return false;
+            }
+        }
+        if !self.filters.kinds.is_empty()
+            && !metadata.kind.as_ref().is_some_and(|k| {
+                self.filters
+                    .kinds
+                    .iter()
+                    .any(|want| want.eq_ignore_ascii_case(k))
+            })
+        {
+            return false;
+        }
+        if let Some(since) = self.filters.modified_since
+            && metadata.modified.is_none_or(|m| m < since)
+        {
+            return false;
+        }
+        true
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -413,6 +795,7 @@ mod tests {
             full_text_weight: 0.5,
             vector_weight: 0.5,
             max_results: 10,
+            ..Default::default()
         }
     }
 
@@ -595,6 +978,7 @@ mod tests {
             full_text_score: 0.8,
             vector_score: 0.6,
             combined_score: 0.7,
+            explanation: None,
         };
         let json = serde_json::to_string(&result).unwrap();
         let restored: SearchResult = serde_json::from_str(&json).unwrap();
@@ -602,6 +986,224 @@ mod tests {
         assert!((restored.combined_score - 0.7).abs() < f32::EPSILON);
     }
 
+    // -- Query features -----------------------------------------------------
+
+    #[test]
+    fn test_parse_query_phrases() {
+        let parsed = parse_query(r#"retry "exponential  backoff" policy"#);
+        assert_eq!(parsed.terms, vec!["retry", "policy"]);
+        assert_eq!(parsed.phrases, vec!["exponential backoff"]);
+        assert!(parsed.matches("Policy: retry with exponential backoff"));
+        assert!(!parsed.matches("retry policy with backoff"));
+
+        // An unmatched quote is dropped.
+        let parsed = parse_query(r#"open "half"#);
+        assert_eq!(parsed.terms, vec!["open", "half"]);
+        assert!(parsed.phrases.is_empty());
+    }
+
+    /// A small corpus whose top results are pinned by
+    /// `test_relevance_regression`; changes to scoring that move them should
+    /// be deliberate.
+    fn fixture_engine() -> HybridSearchEngine {
+        let base = temp_config();
+        let mut engine = HybridSearchEngine::open(SearchConfig {
+            index_path: base.index_path,
+            db_path: base.db_path,
+            ..Default::default()
+        })
+        .unwrap();
+        let day = |d: u32| {
+            chrono::NaiveDate::from_ymd_opt(2026, 3, d)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let corpus = [
+            (
+                "auth:login",
+                "pub fn login(user: &str, password: &str) -> Result<Session, AuthError>",
+                "src/auth/login.rs",
+                "rust",
+                Some("function"),
+                1,
+            ),
+            (
+                "auth:refresh",
+                "pub fn refresh_token(session: &Session) -> Result<Token, AuthError>",
+                "src/auth/token.rs",
+                "rust",
+                Some("function"),
+                20,
+            ),
+            (
+                "db:pool",
+                "pub struct ConnectionPool { max_connections: usize }",
+                "src/db/pool.rs",
+                "rust",
+                Some("struct"),
+                2,
+            ),
+            (
+                "db:migrate",
+                "pub fn run_migrations(pool: &ConnectionPool) -> Result<(), DbError>",
+                "src/db/migrate.rs",
+                "rust",
+                Some("function"),
+                3,
+            ),
+            (
+                "web:retry",
+                "export function retryWithBackoff(request, attempts) { /* exponential backoff */ }",
+                "web/src/retry.ts",
+                "typescript",
+                Some("function"),
+                4,
+            ),
+            (
+                "docs:auth",
+                "The login flow issues a session token; call refresh before it expires.",
+                "docs/auth.md",
+                "markdown",
+                None,
+                5,
+            ),
+            (
+                "cfg:db",
+                "max connections for the database pool",
+                "config/db.toml",
+                "toml",
+                None,
+                6,
+            ),
+        ];
+        for (id, content, path, language, kind, modified) in corpus {
+            let metadata = FactMetadata {
+                path: Some(path.to_string()),
+                language: Some(language.to_string()),
+                kind: kind.map(str::to_string),
+                modified: Some(day(modified)),
+            };
+            engine.index_fact_with(id, content, metadata).unwrap();
+        }
+        engine
+    }
+
+    fn top_ids(engine: &HybridSearchEngine, query: SearchQuery) -> Vec<String> {
+        engine
+            .search_query(&query)
+            .unwrap()
+            .results
+            .into_iter()
+            .map(|r| r.fact_id)
+            .collect()
+    }
+
+    #[test]
+    fn test_relevance_regression() {
+        let engine = fixture_engine();
+        let since = |d: u32| {
+            chrono::NaiveDate::from_ymd_opt(2026, 3, d)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+
+        // A term only one fact contains.
+        let ids = top_ids(&engine, SearchQuery::new("migrations"));
+        assert_eq!(ids[0], "db:migrate");
+
+        // An exact phrase outranks the same words apart.
+        let ids = top_ids(&engine, SearchQuery::new(r#""session token""#));
+        assert_eq!(ids[0], "docs:auth");
+
+        // Symbol kind: the struct, not the function taking it.
+        let mut query = SearchQuery::new("ConnectionPool");
+        query.filters.kinds = vec!["struct".into()];
+        assert_eq!(top_ids(&engine, query), vec!["db:pool"]);
+
+        // Language by name, path by glob.
+        let mut query = SearchQuery::new("login session");
+        query.filters.language = Some("markdown".into());
+        assert_eq!(top_ids(&engine, query), vec!["docs:auth"]);
+        let mut query = SearchQuery::new("pool");
+        query.filters.paths = vec!["src/db/**".into()];
+        let ids = top_ids(&engine, query);
+        assert_eq!(ids[0], "db:migrate");
+        assert!(ids.iter().all(|id| id.starts_with("db:")));
+
+        // Modified-since drops the older doc mentioning tokens.
+        let mut query = SearchQuery::new("token");
+        query.filters.modified_since = Some(since(10));
+        assert_eq!(top_ids(&engine, query), vec!["auth:refresh"]);
+
+        // Facts without metadata never pass a filter.
+        let mut engine = engine;
+        engine.index_fact("bare", "pool of workers").unwrap();
+        let mut query = SearchQuery::new("pool");
+        query.filters.language = Some("rs".into());
+        assert!(!top_ids(&engine, query).contains(&"bare".to_string()));
+    }
+
+    #[test]
+    fn test_search_pages_are_stable() {
+        let engine = fixture_engine();
+        let page = |offset: usize, limit: usize| {
+            let mut query = SearchQuery::new("pub fn");
+            query.offset = offset;
+            query.limit = Some(limit);
+            engine.search_query(&query).unwrap()
+        };
+        let all = page(0, 10);
+        let first = page(0, 2);
+        let second = page(2, 2);
+        assert_eq!(first.total, all.total);
+        assert_eq!(first.next_offset, Some(2));
+        let ids: Vec<&str> = first
+            .results
+            .iter()
+            .chain(&second.results)
+            .map(|r| r.fact_id.as_str())
+            .collect();
+        let expected: Vec<&str> = all
+            .results
+            .iter()
+            .take(4)
+            .map(|r| r.fact_id.as_str())
+            .collect();
+        assert_eq!(ids, expected);
+
+        let last = page(all.total, 2);
+        assert!(last.results.is_empty());
+        assert_eq!(last.next_offset, None);
+    }
+
+    #[test]
+    fn test_search_explanation() {
+        let engine = fixture_engine();
+        let results = engine.search("login").unwrap();
+        assert!(results.iter().all(|r| r.explanation.is_none()));
+
+        let mut query = SearchQuery::new(r#"login "session token""#);
+        query.explain = true;
+        let page = engine.search_query(&query).unwrap();
+        let docs = page
+            .results
+            .iter()
+            .find(|r| r.fact_id == "docs:auth")
+            .unwrap();
+        let explanation = docs.explanation.as_ref().unwrap();
+        assert!(explanation.lexical_score > 0.0);
+        assert!(explanation.semantic_score > 0.0);
+        assert!((explanation.phrase_boost - 1.0).abs() < f32::EPSILON);
+        assert!((explanation.final_score - docs.combined_score).abs() < f32::EPSILON);
+        assert_eq!(explanation.matched_phrases, vec!["session token"]);
+        assert!(explanation.matched_terms.contains(&"login".to_string()));
+        assert!(explanation.to_string().contains("phrase +1.00"));
+    }
+
     #[test]
     fn test_search_error_display() {
         let err = SearchError::IndexError("test error".into());
@@ -139,6 +139,8 @@ pub struct MigrationResult {
     pub already_secure: usize,
     /// Errors encountered during migration.
     pub errors: Vec<String>,
+    /// Accounts written to the destination store.
+    pub moved: Vec<String>,
 }
 
 impl std::fmt::Display for MigrationResult {
@@ -185,6 +187,7 @@ pub fn migrate_channel_secrets(
                     Ok(()) => {
                         tracing::info!(account = account, "Migrated secret to keychain");
                         result.migrated += 1;
+                        result.moved.push(account.to_string());
                     }
                     Err(e) => {
                         result
@@ -205,6 +208,59 @@ pub fn migrate_channel_secrets(
     result
 }
 
+/// Move credentials between stores, e.g. from environment variables or the
+/// OS keychain into the encrypted file.
+///
+/// Accounts missing from `from` are skipped, and accounts `to` already holds
+/// with the same value count as already secure. A different value in `to` is
+/// an error rather than being overwritten. Unless `keep_source` is set, each
+/// moved account is deleted from `from`.
+pub fn migrate_credentials(
+    accounts: &[String],
+    from: &dyn CredentialStore,
+    to: &dyn CredentialStore,
+    keep_source: bool,
+) -> MigrationResult {
+    let mut result = MigrationResult::default();
+
+    for account in accounts {
+        let Ok(value) = from.get_key(account) else {
+            continue;
+        };
+        match to.get_key(account) {
+            Ok(existing) if existing == value => {
+                result.already_secure += 1;
+                continue;
+            }
+            Ok(_) => {
+                result.errors.push(format!(
+                    "{} already has a different value in the destination; delete it first",
+                    account
+                ));
+                continue;
+            }
+            Err(_) => {}
+        }
+        if let Err(e) = to.store_key(account, &value) {
+            result
+                .errors
+                .push(format!("Failed to store {}: {}", account, e));
+            continue;
+        }
+        tracing::info!(account = account.as_str(), "Migrated credential");
+        result.migrated += 1;
+        result.moved.push(account.clone());
+        if !keep_source && let Err(e) = from.delete_key(account) {
+            result.errors.push(format!(
+                "Copied {} but could not remove it from the source: {}",
+                account, e
+            ));
+        }
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -343,4 +399,43 @@ mod tests {
             "my-email-password"
         );
     }
+
+    #[test]
+    fn test_migrate_credentials_moves_and_skips() {
+        let from = InMemoryCredentialStore::new();
+        let to = InMemoryCredentialStore::new();
+        from.store_key("openai", "sk-a").unwrap();
+        from.store_key("anthropic", "sk-b").unwrap();
+        from.store_key("gemini", "sk-c").unwrap();
+        to.store_key("anthropic", "sk-b").unwrap();
+        to.store_key("gemini", "sk-other").unwrap();
+
+        let accounts: Vec<String> = ["openai", "anthropic", "gemini", "missing"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        let result = migrate_credentials(&accounts, &from, &to, false);
+
+        assert_eq!(result.moved, vec!["openai".to_string()]);
+        assert_eq!(result.already_secure, 1);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].starts_with("gemini"));
+        assert_eq!(to.get_key("openai").unwrap(), "sk-a");
+        assert!(!from.has_key("openai"));
+        // Conflicting and already-present accounts stay in the source
+        assert!(from.has_key("gemini"));
+        assert!(from.has_key("anthropic"));
+    }
+
+    #[test]
+    fn test_migrate_credentials_keep_source() {
+        let from = InMemoryCredentialStore::new();
+        let to = InMemoryCredentialStore::new();
+        from.store_key("openai", "sk-a").unwrap();
+
+        let result = migrate_credentials(&["openai".to_string()], &from, &to, true);
+        assert_eq!(result.migrated, 1);
+        assert!(from.has_key("openai"));
+        assert!(to.has_key("openai"));
+    }
 }
@@ -3,14 +3,75 @@
 //! Maintains a session index in the sessions directory with metadata (name,
 //! last task, timestamp, token usage, completion status). Supports auto-save,
 //! listing, resume, rename, and delete operations.
-
+//!
+//! With `[sessions] encrypt_at_rest` (the default), transcripts, turn
+//! recordings and memory snapshots are sealed with the workspace session
+//! key. `index.json` then keeps only metadata (names, timestamps, token
+//! counts, tags); goals, summaries and artifacts move to the encrypted
+//! `index.sealed`, so listing stays cheap while full-text search decrypts
+//! transcripts on demand.
+
+use crate::credentials::CredentialStore;
+use crate::encryption::{
+    EncryptionError, begin_key_rotation, create_session_key, finish_key_rotation, is_sealed,
+    load_session_key, session_key_account,
+};
 use crate::error::MemoryError;
 use crate::memory::MemorySystem;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+/// Encrypted companion of `index.json` holding each entry's sensitive fields.
+const SEALED_INDEX_FILE: &str = "index.sealed";
+
+/// Session persistence settings (`[sessions]`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionsConfig {
+    /// Encrypt transcripts, recordings and sensitive index fields with the
+    /// workspace session key.
+    #[serde(default = "default_encrypt_at_rest")]
+    pub encrypt_at_rest: bool,
+}
+
+fn default_encrypt_at_rest() -> bool {
+    true
+}
+
+impl Default for SessionsConfig {
+    fn default() -> Self {
+        Self {
+            encrypt_at_rest: default_encrypt_at_rest(),
+        }
+    }
+}
+
+/// Index fields kept out of `index.json` when encryption is on.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SealedFields {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_goal: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    artifacts: Vec<crate::artifacts::ArtifactRecord>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    manifest: BTreeMap<String, String>,
+}
+
+/// Where a branch was forked off its parent session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BranchPoint {
+    /// The session this one was forked from.
+    pub parent_id: Uuid,
+    /// Number of user turns kept from the parent.
+    pub turn: usize,
+    /// Timestamp of the first parent message left out of the branch.
+    pub forked_at: DateTime<Utc>,
+}
+
 /// Metadata for a session entry in the session index.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionEntry {
@@ -40,6 +101,23 @@ pub struct SessionEntry {
     /// Auto-detected project type at save time.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub project_type: Option<String>,
+    /// Persona active when the session was last saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persona: Option<String>,
+    /// Artifacts produced during the session.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<crate::artifacts::ArtifactRecord>,
+    /// SHA-256 of each artifact file when the artifacts were last saved,
+    /// keyed by path, so branches can be compared after the shared
+    /// workspace has moved on.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub manifest: BTreeMap<String, String>,
+    /// Plan task runs, with continuations linked to the run they resumed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub task_runs: Vec<crate::task_checkpoint::TaskRun>,
+    /// Set when the session was forked from another one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<BranchPoint>,
 }
 
 /// The session index stored as a JSON file.
@@ -109,9 +187,41 @@ impl SessionIndex {
     /// List entries sorted by most recently updated.
     pub fn list_recent(&self, limit: usize) -> Vec<&SessionEntry> {
         let mut entries: Vec<&SessionEntry> = self.entries.iter().collect();
-        entries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        entries.sort_by_key(|e| std::cmp::Reverse(e.updated_at));
         entries.into_iter().take(limit).collect()
     }
+
+    /// The most recently updated `limit` session trees, each root followed
+    /// depth-first by its branches (oldest fork first), paired with their
+    /// depth. Branches whose parent was deleted are listed as roots.
+    pub fn list_tree(&self, limit: usize) -> Vec<(usize, &SessionEntry)> {
+        let parent_of = |e: &SessionEntry| {
+            e.branch
+                .as_ref()
+                .map(|b| b.parent_id)
+                .filter(|id| self.find_by_id(*id).is_some())
+        };
+        let mut tree = Vec::new();
+        let roots: Vec<&SessionEntry> = self
+            .list_recent(self.entries.len())
+            .into_iter()
+            .filter(|e| parent_of(e).is_none())
+            .take(limit)
+            .collect();
+        let mut stack: Vec<(usize, &SessionEntry)> =
+            roots.into_iter().rev().map(|e| (0, e)).collect();
+        while let Some((depth, entry)) = stack.pop() {
+            tree.push((depth, entry));
+            let mut children: Vec<&SessionEntry> = self
+                .entries
+                .iter()
+                .filter(|e| parent_of(e) == Some(entry.id))
+                .collect();
+            children.sort_by_key(|e| std::cmp::Reverse(e.created_at));
+            stack.extend(children.into_iter().map(|e| (depth + 1, e)));
+        }
+        tree
+    }
 }
 
 /// Manages session persistence, indexing, and resume.
@@ -122,6 +232,8 @@ pub struct SessionManager {
     active_session_id: Option<Uuid>,
     /// Optional encryptor for session data at rest.
     encryptor: Option<crate::encryption::SessionEncryptor>,
+    /// The sealed index could not be decrypted; leave it untouched on save.
+    sealed_unreadable: bool,
 }
 
 impl SessionManager {
@@ -134,6 +246,7 @@ impl SessionManager {
             index,
             active_session_id: None,
             encryptor: None,
+            sealed_unreadable: false,
         })
     }
 
@@ -145,15 +258,90 @@ impl SessionManager {
             index,
             active_session_id: None,
             encryptor: None,
+            sealed_unreadable: false,
         })
     }
 
+    /// Open the sessions of `workspace`, encrypted at rest when
+    /// `config.encrypt_at_rest` is set, with the workspace session key from
+    /// the configured credential store.
+    pub fn open(workspace: &Path, config: &SessionsConfig) -> Result<Self, MemoryError> {
+        let manager = Self::new(workspace)?;
+        if !config.encrypt_at_rest {
+            return Ok(manager);
+        }
+        manager.with_workspace_key(&*crate::credentials::configured_store(), workspace)
+    }
+
+    /// Encrypt with the session key of `workspace` held in `store`,
+    /// creating the key if nothing has been encrypted yet.
+    ///
+    /// A missing key is an error once encrypted sessions exist. If the
+    /// store itself is unreachable and nothing is encrypted yet, sessions
+    /// stay in plaintext rather than becoming unusable.
+    pub fn with_workspace_key(
+        mut self,
+        store: &dyn CredentialStore,
+        workspace: &Path,
+    ) -> Result<Self, MemoryError> {
+        let encryptor = match load_session_key(store, workspace) {
+            Ok(Some(encryptor)) => encryptor,
+            Ok(None) if self.has_encrypted_data() => {
+                return Err(MemoryError::SessionKeyUnavailable {
+                    message: format!(
+                        "{} holds encrypted sessions but the credential store has no key {}",
+                        self.sessions_dir.display(),
+                        session_key_account(workspace)
+                    ),
+                });
+            }
+            Ok(None) => create_session_key(store, workspace).map_err(key_unavailable)?,
+            Err(e) if self.has_encrypted_data() => return Err(key_unavailable(e)),
+            Err(e) => {
+                tracing::warn!("Session encryption disabled: {}", e);
+                return Ok(self);
+            }
+        };
+        self.encryptor = Some(encryptor);
+        self.unseal_index()?;
+        Ok(self)
+    }
+
     /// Enable encryption for session data using the provided encryptor.
     pub fn with_encryption(mut self, encryptor: crate::encryption::SessionEncryptor) -> Self {
         self.encryptor = Some(encryptor);
+        self.sealed_unreadable = self.unseal_index().is_err();
         self
     }
 
+    /// Whether session data is encrypted on save.
+    pub fn is_encrypted(&self) -> bool {
+        self.encryptor.is_some()
+    }
+
+    /// Whether anything in the sessions directory is encrypted.
+    pub fn has_encrypted_data(&self) -> bool {
+        self.sessions_dir.join(SEALED_INDEX_FILE).exists()
+            || self
+                .index
+                .entries
+                .iter()
+                .any(|e| file_is_sealed(&self.sessions_dir.join(&e.file_name)))
+    }
+
+    /// Load the index of `workspace` for reading, with sealed fields filled
+    /// in when its session key is available. Never creates a key.
+    pub fn read_index(workspace: &Path) -> Result<SessionIndex, MemoryError> {
+        let manager = Self::new(workspace)?;
+        if !manager.sessions_dir.join(SEALED_INDEX_FILE).exists() {
+            return Ok(manager.index);
+        }
+        match load_session_key(&*crate::credentials::configured_store(), workspace) {
+            Ok(Some(encryptor)) => Ok(manager.with_encryption(encryptor).index),
+            _ => Ok(manager.index),
+        }
+    }
+
     /// Start a new session with an optional name.
     pub fn start_session(&mut self, name: Option<&str>) -> SessionEntry {
         let id = Uuid::new_v4();
@@ -176,11 +364,16 @@ impl SessionManager {
             file_name,
             tags: Vec::new(),
             project_type: None,
+            persona: None,
+            artifacts: Vec::new(),
+            manifest: BTreeMap::new(),
+            task_runs: Vec::new(),
+            branch: None,
         };
 
         self.index.entries.push(entry.clone());
         self.active_session_id = Some(id);
-        let _ = self.index.save(&self.sessions_dir);
+        let _ = self.save_index();
         entry
     }
 
@@ -212,35 +405,12 @@ impl SessionManager {
         entry.message_count = memory.short_term.len();
         entry.total_tokens = total_tokens;
 
-        // Save session data
+        // Save session data, encrypted if encryption is enabled
         let session_path = self.sessions_dir.join(&entry.file_name);
-        memory.save_session(&session_path)?;
-
-        // Encrypt session file if encryption is enabled
-        if let Some(ref encryptor) = self.encryptor {
-            let plaintext =
-                std::fs::read(&session_path).map_err(|e| MemoryError::PersistenceError {
-                    message: format!("Failed to read session for encryption: {}", e),
-                })?;
-            let encrypted =
-                encryptor
-                    .encrypt(&plaintext)
-                    .map_err(|e| MemoryError::PersistenceError {
-                        message: format!("Failed to encrypt session: {}", e),
-                    })?;
-            let tmp_path = session_path.with_extension("json.enc.tmp");
-            std::fs::write(&tmp_path, &encrypted).map_err(|e| MemoryError::PersistenceError {
-                message: format!("Failed to write encrypted session: {}", e),
-            })?;
-            std::fs::rename(&tmp_path, &session_path).map_err(|e| {
-                MemoryError::PersistenceError {
-                    message: format!("Failed to finalize encrypted session: {}", e),
-                }
-            })?;
-        }
+        self.write_memory(memory, &session_path)?;
 
         // Save updated index
-        self.index.save(&self.sessions_dir)
+        self.save_index()
     }
 
     /// Mark the active session as completed.
@@ -251,7 +421,7 @@ impl SessionManager {
                 entry.updated_at = Utc::now();
                 entry.summary = summary;
             }
-            self.index.save(&self.sessions_dir)?;
+            self.save_index()?;
         }
         Ok(())
     }
@@ -275,30 +445,7 @@ impl SessionManager {
         };
 
         let session_path = self.sessions_dir.join(&entry.file_name);
-
-        // Decrypt session file if encryption is enabled
-        let memory = if let Some(ref encryptor) = self.encryptor {
-            let encrypted =
-                std::fs::read(&session_path).map_err(|e| MemoryError::SessionLoadFailed {
-                    message: format!("Failed to read encrypted session: {}", e),
-                })?;
-            let plaintext =
-                encryptor
-                    .decrypt(&encrypted)
-                    .map_err(|e| MemoryError::SessionLoadFailed {
-                        message: format!("Failed to decrypt session: {}", e),
-                    })?;
-            // Write decrypted data to a temp file for loading
-            let tmp_path = session_path.with_extension("json.dec.tmp");
-            std::fs::write(&tmp_path, &plaintext).map_err(|e| MemoryError::SessionLoadFailed {
-                message: format!("Failed to write decrypted session: {}", e),
-            })?;
-            let result = MemorySystem::load_session(&tmp_path);
-            let _ = std::fs::remove_file(&tmp_path); // Clean up temp file
-            result?
-        } else {
-            MemorySystem::load_session(&session_path)?
-        };
+        let memory = self.read_memory(&session_path)?;
 
         // Build continuation prompt
         let mut continuation =
@@ -359,7 +506,7 @@ impl SessionManager {
         match entry {
             Some(e) => {
                 e.name = new_name.to_string();
-                self.index.save(&self.sessions_dir)
+                self.save_index()
             }
             None => Err(MemoryError::SessionLoadFailed {
                 message: format!("No session found matching: '{}'", query),
@@ -393,15 +540,19 @@ impl SessionManager {
             }
         };
 
-        // Remove session data file
+        // Remove session data file and its turn recording
         let session_path = self.sessions_dir.join(&file_name);
         if session_path.exists() {
             let _ = std::fs::remove_file(&session_path);
         }
+        let recording_path = self.sessions_dir.join(recording_file_name(&file_name));
+        if recording_path.exists() {
+            let _ = std::fs::remove_file(&recording_path);
+        }
 
         // Remove from index
         self.index.entries.remove(idx);
-        self.index.save(&self.sessions_dir)?;
+        self.save_index()?;
 
         Ok(name)
     }
@@ -429,6 +580,7 @@ impl SessionManager {
             index,
             active_session_id: None,
             encryptor: None,
+            sealed_unreadable: false,
         }
     }
 
@@ -477,6 +629,540 @@ impl SessionManager {
             .collect()
     }
 
+    /// Record the persona in use on the active session.
+    pub fn set_active_persona(&mut self, persona: Option<&str>) -> Result<(), MemoryError> {
+        let Some(session_id) = self.active_session_id else {
+            return Ok(());
+        };
+        if let Some(entry) = self.index.entries.iter_mut().find(|e| e.id == session_id) {
+            entry.persona = persona.map(|p| p.to_string());
+        }
+        self.save_index()
+    }
+
+    /// Record the artifacts produced on the active session, with a manifest
+    /// of their current file contents.
+    pub fn set_artifacts(
+        &mut self,
+        artifacts: &[crate::artifacts::ArtifactRecord],
+    ) -> Result<(), MemoryError> {
+        let Some(session_id) = self.active_session_id else {
+            return Ok(());
+        };
+        if let Some(entry) = self.index.entries.iter_mut().find(|e| e.id == session_id) {
+            entry.artifacts = artifacts.to_vec();
+            entry.manifest = artifact_manifest(artifacts);
+        }
+        self.save_index()
+    }
+
+    /// Artifacts recorded on the active session (e.g. after a resume).
+    pub fn active_artifacts(&self) -> Vec<crate::artifacts::ArtifactRecord> {
+        self.active_session_id
+            .and_then(|id| self.index.find_by_id(id))
+            .map(|e| e.artifacts.clone())
+            .unwrap_or_default()
+    }
+
+    /// Record the plan task runs of the active session.
+    pub fn set_task_runs(
+        &mut self,
+        runs: &[crate::task_checkpoint::TaskRun],
+    ) -> Result<(), MemoryError> {
+        let Some(session_id) = self.active_session_id else {
+            return Ok(());
+        };
+        if let Some(entry) = self.index.entries.iter_mut().find(|e| e.id == session_id) {
+            entry.task_runs = runs.to_vec();
+        }
+        self.save_index()
+    }
+
+    /// Task runs recorded on the active session (e.g. after a resume).
+    pub fn active_task_runs(&self) -> Vec<crate::task_checkpoint::TaskRun> {
+        self.active_session_id
+            .and_then(|id| self.index.find_by_id(id))
+            .map(|e| e.task_runs.clone())
+            .unwrap_or_default()
+    }
+
+    /// Save the agent's turn recording alongside the active session, for
+    /// `rustant sessions replay`.
+    pub fn save_recording(
+        &self,
+        recording: &crate::replay::SessionRecording,
+    ) -> Result<(), MemoryError> {
+        let Some(entry) = self
+            .active_session_id
+            .and_then(|id| self.index.find_by_id(id))
+        else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec(recording).map_err(|e| MemoryError::PersistenceError {
+            message: format!("Failed to serialize recording: {}", e),
+        })?;
+        let path = self
+            .sessions_dir
+            .join(recording_file_name(&entry.file_name));
+        self.write_data(&path, &json)
+    }
+
+    /// Load the turn recording of a session by name or ID.
+    ///
+    /// Sessions saved before recordings existed fail with
+    /// [`ReplayError::NotRecorded`](crate::replay::ReplayError::NotRecorded).
+    pub fn load_recording(
+        &self,
+        query: &str,
+    ) -> Result<(SessionEntry, crate::replay::SessionRecording), crate::replay::ReplayError> {
+        use crate::replay::ReplayError;
+
+        let entry = self
+            .find_entry(query)
+            .cloned()
+            .ok_or_else(|| ReplayError::SessionNotFound(query.to_string()))?;
+        let path = self
+            .sessions_dir
+            .join(recording_file_name(&entry.file_name));
+        if !path.exists() {
+            return Err(ReplayError::NotRecorded(entry.name));
+        }
+        let data = self
+            .read_data(&path)
+            .map_err(|e| ReplayError::LoadFailed(e.to_string()))?;
+        let recording = crate::replay::SessionRecording::from_json(&entry.name, &data)?;
+        Ok((entry, recording))
+    }
+
+    /// The turn recording of the active session, if it has one (e.g. after
+    /// a resume, so new turns extend it).
+    pub fn active_recording(&self) -> Option<crate::replay::SessionRecording> {
+        let id = self.active_session_id?;
+        self.load_recording(&id.to_string())
+            .map(|(_, recording)| recording)
+            .ok()
+    }
+
+    /// Search session transcripts, decrypting each one on demand. Slower
+    /// than [`search`](Self::search), which only reads the index.
+    pub fn search_transcripts(&self, query: &str) -> Vec<&SessionEntry> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        self.index
+            .entries
+            .iter()
+            .filter(|e| {
+                let Ok(data) = self.read_data(&self.sessions_dir.join(&e.file_name)) else {
+                    return false;
+                };
+                let Ok(session) = serde_json::from_slice::<crate::memory::Session>(&data) else {
+                    return false;
+                };
+                session.messages.iter().any(|m| {
+                    let text = match m.content.as_text() {
+                        Some(text) => text.to_lowercase(),
+                        None => serde_json::to_string(&m.content)
+                            .unwrap_or_default()
+                            .to_lowercase(),
+                    };
+                    text.contains(&query)
+                })
+            })
+            .collect()
+    }
+
+    /// A session's index entry, transcript and turn recording as one JSON
+    /// document, decrypted, for `rustant sessions export`.
+    pub fn export_session(&self, query: &str) -> Result<serde_json::Value, MemoryError> {
+        let entry = self
+            .find_entry(query)
+            .ok_or_else(|| MemoryError::SessionLoadFailed {
+                message: format!("No session found matching: '{}'", query),
+            })?;
+        let parse = |data: Vec<u8>| {
+            serde_json::from_slice::<serde_json::Value>(&data).map_err(|e| {
+                MemoryError::SessionLoadFailed {
+                    message: format!("Failed to parse session '{}': {}", entry.name, e),
+                }
+            })
+        };
+        let session = parse(self.read_data(&self.sessions_dir.join(&entry.file_name))?)?;
+        let recording_path = self
+            .sessions_dir
+            .join(recording_file_name(&entry.file_name));
+        let recording = if recording_path.exists() {
+            Some(parse(self.read_data(&recording_path)?)?)
+        } else {
+            None
+        };
+        Ok(serde_json::json!({
+            "entry": entry,
+            "session": session,
+            "recording": recording,
+        }))
+    }
+
+    /// Write a memory snapshot to `path`, encrypted when encryption is on.
+    /// Also used for snapshots kept outside the index, such as autosaves.
+    pub fn write_memory(&self, memory: &MemorySystem, path: &Path) -> Result<(), MemoryError> {
+        self.write_data(path, memory.to_session_json()?.as_bytes())
+    }
+
+    /// Read a snapshot written by [`write_memory`](Self::write_memory), or a
+    /// plaintext one saved before encryption was enabled.
+    pub fn read_memory(&self, path: &Path) -> Result<MemorySystem, MemoryError> {
+        let data = self.read_data(path)?;
+        let json = String::from_utf8(data).map_err(|e| MemoryError::SessionLoadFailed {
+            message: format!("Session data is not UTF-8: {}", e),
+        })?;
+        MemorySystem::from_session_json(&json)
+    }
+
+    /// Read a session data file, decrypting it if it is encrypted.
+    /// Plaintext JSON written before encryption was enabled is read as is.
+    fn read_data(&self, path: &Path) -> Result<Vec<u8>, MemoryError> {
+        let data = std::fs::read(path).map_err(|e| MemoryError::SessionLoadFailed {
+            message: format!("Failed to read {}: {}", path.display(), e),
+        })?;
+        if !is_sealed(&data) && serde_json::from_slice::<serde::de::IgnoredAny>(&data).is_ok() {
+            return Ok(data);
+        }
+        self.encryptor
+            .as_ref()
+            .ok_or(EncryptionError::NoKey)
+            .and_then(|encryptor| encryptor.unseal(&data))
+            .map_err(|e| match e {
+                EncryptionError::NoKey | EncryptionError::KeyMismatch(_) => key_unavailable(e),
+                e => MemoryError::SessionLoadFailed {
+                    message: format!("Failed to decrypt {}: {}", path.display(), e),
+                },
+            })
+    }
+
+    /// Write a session data file atomically, encrypted when encryption is on.
+    fn write_data(&self, path: &Path, plaintext: &[u8]) -> Result<(), MemoryError> {
+        let data = match &self.encryptor {
+            Some(encryptor) => {
+                encryptor
+                    .seal(plaintext)
+                    .map_err(|e| MemoryError::PersistenceError {
+                        message: format!("Failed to encrypt session data: {}", e),
+                    })?
+            }
+            None => plaintext.to_vec(),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| MemoryError::PersistenceError {
+                message: format!("Failed to create directory: {}", e),
+            })?;
+        }
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let tmp_path = path.with_file_name(format!(".{}.tmp", file_name));
+        std::fs::write(&tmp_path, &data).map_err(|e| MemoryError::PersistenceError {
+            message: format!("Failed to write {}: {}", path.display(), e),
+        })?;
+        std::fs::rename(&tmp_path, path).map_err(|e| MemoryError::PersistenceError {
+            message: format!("Failed to finalize {}: {}", path.display(), e),
+        })
+    }
+
+    /// Save the index. With encryption on, goals, summaries and artifacts
+    /// go to the sealed index and `index.json` keeps only metadata.
+    fn save_index(&self) -> Result<(), MemoryError> {
+        if self.encryptor.is_none() {
+            return self.index.save(&self.sessions_dir);
+        }
+        let mut plain = self.index.clone();
+        let mut sealed = HashMap::new();
+        for entry in &mut plain.entries {
+            sealed.insert(
+                entry.id,
+                SealedFields {
+                    last_goal: entry.last_goal.take(),
+                    summary: entry.summary.take(),
+                    artifacts: std::mem::take(&mut entry.artifacts),
+                    manifest: std::mem::take(&mut entry.manifest),
+                },
+            );
+        }
+        if !self.sealed_unreadable {
+            let json = serde_json::to_vec(&sealed).map_err(|e| MemoryError::PersistenceError {
+                message: format!("Failed to serialize sealed index: {}", e),
+            })?;
+            self.write_data(&self.sessions_dir.join(SEALED_INDEX_FILE), &json)?;
+        }
+        plain.save(&self.sessions_dir)
+    }
+
+    /// Fill in the loaded index from the sealed index, if there is one.
+    fn unseal_index(&mut self) -> Result<(), MemoryError> {
+        let path = self.sessions_dir.join(SEALED_INDEX_FILE);
+        if !path.exists() {
+            return Ok(());
+        }
+        let data = self.read_data(&path)?;
+        let mut sealed: HashMap<Uuid, SealedFields> =
+            serde_json::from_slice(&data).map_err(|e| MemoryError::SessionLoadFailed {
+                message: format!("Failed to parse sealed index: {}", e),
+            })?;
+        for entry in &mut self.index.entries {
+            if let Some(fields) = sealed.remove(&entry.id) {
+                entry.last_goal = fields.last_goal;
+                entry.summary = fields.summary;
+                entry.artifacts = fields.artifacts;
+                entry.manifest = fields.manifest;
+            }
+        }
+        Ok(())
+    }
+
+    /// Find a session by ID, or by name (exact, then prefix).
+    fn find_entry(&self, query: &str) -> Option<&SessionEntry> {
+        match Uuid::parse_str(query) {
+            Ok(id) => self.index.find_by_id(id),
+            Err(_) => self.index.find_by_name(query),
+        }
+    }
+
+    /// Fork a session after its first `turn` user turns into a new branch,
+    /// which becomes the active session.
+    ///
+    /// The branch gets the transcript up to that point, the long-term facts
+    /// and corrections recorded before it, and the artifacts produced before
+    /// it. Working memory belongs to the later task and is dropped.
+    /// Artifact records are shared, not the files they point at, so nothing
+    /// in the workspace is copied. The turn recording is truncated the same
+    /// way.
+    pub fn fork_session(
+        &mut self,
+        query: &str,
+        turn: usize,
+        name: Option<&str>,
+    ) -> Result<SessionEntry, MemoryError> {
+        let parent =
+            self.find_entry(query)
+                .cloned()
+                .ok_or_else(|| MemoryError::SessionLoadFailed {
+                    message: format!("No session found matching: '{}'", query),
+                })?;
+        let data = self.read_data(&self.sessions_dir.join(&parent.file_name))?;
+        let mut session: crate::memory::Session =
+            serde_json::from_slice(&data).map_err(|e| MemoryError::SessionLoadFailed {
+                message: format!("Failed to parse session '{}': {}", parent.name, e),
+            })?;
+
+        let user_turns: Vec<usize> = session
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.role == crate::types::Role::User)
+            .map(|(i, _)| i)
+            .collect();
+        if turn == 0 || turn > user_turns.len() {
+            return Err(MemoryError::SessionLoadFailed {
+                message: format!(
+                    "Session '{}' has {} turns; pick a turn from 1 to {}",
+                    parent.name,
+                    user_turns.len(),
+                    user_turns.len()
+                ),
+            });
+        }
+        let forked_at = match user_turns.get(turn) {
+            Some(&cut) => {
+                let forked_at = session.messages[cut].timestamp;
+                session.messages.truncate(cut);
+                session.working = crate::memory::WorkingMemory::default();
+                forked_at
+            }
+            None => Utc::now(),
+        };
+        session.long_term.facts.retain(|f| f.created_at < forked_at);
+        session
+            .long_term
+            .corrections
+            .retain(|c| c.timestamp < forked_at);
+        session.metadata = crate::memory::SessionMetadata::new();
+
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let file_name = format!("{}.json", id);
+        let json =
+            serde_json::to_vec_pretty(&session).map_err(|e| MemoryError::PersistenceError {
+                message: format!("Failed to serialize session: {}", e),
+            })?;
+        self.write_data(&self.sessions_dir.join(&file_name), &json)?;
+
+        let mut total_tokens = 0;
+        if let Ok((_, recording)) = self.load_recording(&parent.id.to_string()) {
+            let recording = recording.truncated(forked_at);
+            total_tokens = recording.turns.iter().map(|t| t.usage.total()).sum();
+            let json =
+                serde_json::to_vec(&recording).map_err(|e| MemoryError::PersistenceError {
+                    message: format!("Failed to serialize recording: {}", e),
+                })?;
+            self.write_data(
+                &self.sessions_dir.join(recording_file_name(&file_name)),
+                &json,
+            )?;
+        }
+
+        let artifacts: Vec<_> = parent
+            .artifacts
+            .iter()
+            .filter(|a| a.created_at < forked_at)
+            .cloned()
+            .collect();
+        let manifest = parent
+            .manifest
+            .iter()
+            .filter(|(path, _)| {
+                artifacts.iter().any(|a| {
+                    a.path
+                        .as_ref()
+                        .is_some_and(|p| p.to_string_lossy() == **path)
+                })
+            })
+            .map(|(path, hash)| (path.clone(), hash.clone()))
+            .collect();
+        let entry = SessionEntry {
+            id,
+            name: name
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| format!("{}-fork-{}", parent.name, turn)),
+            created_at: now,
+            updated_at: now,
+            last_goal: session.working.current_goal.clone(),
+            summary: None,
+            message_count: session.messages.len(),
+            total_tokens,
+            completed: false,
+            file_name,
+            tags: parent.tags.clone(),
+            project_type: parent.project_type.clone(),
+            persona: parent.persona.clone(),
+            artifacts,
+            manifest,
+            task_runs: Vec::new(),
+            branch: Some(BranchPoint {
+                parent_id: parent.id,
+                turn,
+                forked_at,
+            }),
+        };
+        self.index.entries.push(entry.clone());
+        self.active_session_id = Some(id);
+        self.save_index()?;
+        Ok(entry)
+    }
+
+    /// Compare what two branches produced after they diverged, from the
+    /// artifact manifests saved with each.
+    ///
+    /// The sessions must be siblings forked from the same parent, or a
+    /// branch and its parent; `right` defaults to the parent of `left`.
+    pub fn compare_branches(
+        &self,
+        left: &str,
+        right: Option<&str>,
+    ) -> Result<BranchComparison, MemoryError> {
+        let find = |query: &str| {
+            self.find_entry(query)
+                .ok_or_else(|| MemoryError::SessionLoadFailed {
+                    message: format!("No session found matching: '{}'", query),
+                })
+        };
+        let left = find(left)?;
+        let right = match right {
+            Some(query) => find(query)?,
+            None => {
+                let parent_id = left.branch.as_ref().map(|b| b.parent_id).ok_or_else(|| {
+                    MemoryError::SessionLoadFailed {
+                        message: format!("Session '{}' is not a branch", left.name),
+                    }
+                })?;
+                self.index
+                    .find_by_id(parent_id)
+                    .ok_or_else(|| MemoryError::SessionLoadFailed {
+                        message: format!("The parent of '{}' no longer exists", left.name),
+                    })?
+            }
+        };
+        if left.id == right.id {
+            return Err(MemoryError::SessionLoadFailed {
+                message: format!("Cannot compare '{}' with itself", left.name),
+            });
+        }
+
+        // Each side's changes start where it diverged from the other: its
+        // own fork point, or the child's fork point on the parent side.
+        let (base_id, left_cut, right_cut) = match (&left.branch, &right.branch) {
+            (Some(l), _) if l.parent_id == right.id => (right.id, l.forked_at, l.forked_at),
+            (_, Some(r)) if r.parent_id == left.id => (left.id, r.forked_at, r.forked_at),
+            (Some(l), Some(r)) if l.parent_id == r.parent_id => {
+                (l.parent_id, l.forked_at, r.forked_at)
+            }
+            _ => {
+                return Err(MemoryError::SessionLoadFailed {
+                    message: format!(
+                        "'{}' and '{}' are not branches of the same session",
+                        left.name, right.name
+                    ),
+                });
+            }
+        };
+        let base = self
+            .index
+            .find_by_id(base_id)
+            .map(|e| e.name.clone())
+            .unwrap_or_else(|| base_id.to_string());
+
+        let changes_of = |entry: &SessionEntry, cut: DateTime<Utc>| {
+            entry
+                .artifacts
+                .iter()
+                .filter(|a| a.created_at >= cut)
+                .map(|a| {
+                    let key = artifact_key(a);
+                    let hash = entry.manifest.get(&key).cloned();
+                    (key, (a.kind, hash))
+                })
+                .collect::<BTreeMap<_, _>>()
+        };
+        let left_changes = changes_of(left, left_cut);
+        let right_changes = changes_of(right, right_cut);
+        let mut keys: Vec<&String> = left_changes.keys().chain(right_changes.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        let changes = keys
+            .into_iter()
+            .filter_map(|key| {
+                let (left, right) = (left_changes.get(key), right_changes.get(key));
+                let (kind, _) = left.or(right)?;
+                let status = match (left, right) {
+                    (Some(_), None) => BranchDiff::OnlyLeft,
+                    (None, _) => BranchDiff::OnlyRight,
+                    (Some((_, Some(l))), Some((_, Some(r)))) if l == r => BranchDiff::Same,
+                    _ => BranchDiff::Differs,
+                };
+                Some(BranchChange {
+                    item: key.clone(),
+                    kind: *kind,
+                    status,
+                })
+            })
+            .collect();
+
+        Ok(BranchComparison {
+            base,
+            left: left.name.clone(),
+            right: right.name.clone(),
+            changes,
+        })
+    }
+
     /// Add a tag to a session.
     pub fn tag_session(&mut self, query: &str, tag: &str) -> Result<(), MemoryError> {
         let query_lower = query.to_lowercase();
@@ -494,7 +1180,7 @@ impl SessionManager {
                 if !e.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag_str)) {
                     e.tags.push(tag_str);
                 }
-                self.index.save(&self.sessions_dir)
+                self.save_index()
             }
             None => Err(MemoryError::SessionLoadFailed {
                 message: format!("No session found matching: '{}'", query),
@@ -503,6 +1189,144 @@ impl SessionManager {
     }
 }
 
+/// How an artifact differs between two branches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchDiff {
+    /// Produced only on the left branch.
+    OnlyLeft,
+    /// Produced only on the right branch.
+    OnlyRight,
+    /// Produced on both with different contents.
+    Differs,
+    /// Produced on both with the same contents.
+    Same,
+}
+
+/// One artifact produced after two branches diverged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchChange {
+    /// File path, or the title of an artifact that is not a file.
+    pub item: String,
+    pub kind: crate::artifacts::ArtifactKind,
+    pub status: BranchDiff,
+}
+
+/// Result of [`SessionManager::compare_branches`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchComparison {
+    /// Name of the session both branches share.
+    pub base: String,
+    pub left: String,
+    pub right: String,
+    pub changes: Vec<BranchChange>,
+}
+
+/// Content hashes of the artifact files that still exist, keyed by path.
+fn artifact_manifest(artifacts: &[crate::artifacts::ArtifactRecord]) -> BTreeMap<String, String> {
+    use crate::pairing::hex;
+    use sha2::{Digest, Sha256};
+
+    artifacts
+        .iter()
+        .filter(|a| !a.stale)
+        .filter_map(|a| {
+            let path = a.path.as_ref()?;
+            let mut file = std::fs::File::open(path).ok()?;
+            let mut hasher = Sha256::new();
+            std::io::copy(&mut file, &mut hasher).ok()?;
+            Some((
+                path.to_string_lossy().into_owned(),
+                hex::encode(hasher.finalize()),
+            ))
+        })
+        .collect()
+}
+
+/// Manifest key of an artifact: its path, or its title when it has none.
+fn artifact_key(artifact: &crate::artifacts::ArtifactRecord) -> String {
+    match &artifact.path {
+        Some(path) => path.to_string_lossy().into_owned(),
+        None => artifact.title.clone(),
+    }
+}
+
+/// Re-encrypt every session file of `workspace` under a new session key,
+/// calling `progress(done, total)` after each file. The clipboard history,
+/// sealed with the same key, is re-encrypted too.
+///
+/// The old key stays in `store` as the previous key until every file is
+/// rewritten, so an interrupted rotation loses nothing and running it again
+/// resumes. Plaintext sessions from before encryption are encrypted too.
+/// Returns the number of files rewritten.
+pub fn rotate_session_key(
+    workspace: &Path,
+    store: &dyn CredentialStore,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<usize, MemoryError> {
+    let mut manager = SessionManager::new(workspace)?;
+    if load_session_key(store, workspace)
+        .map_err(key_unavailable)?
+        .is_none()
+    {
+        if manager.has_encrypted_data() {
+            return Err(key_unavailable(EncryptionError::NoKey));
+        }
+        create_session_key(store, workspace).map_err(key_unavailable)?;
+    }
+    manager.encryptor = Some(begin_key_rotation(store, workspace).map_err(key_unavailable)?);
+    manager.unseal_index()?;
+
+    let files: Vec<PathBuf> = manager
+        .index
+        .entries
+        .iter()
+        .flat_map(|e| [e.file_name.clone(), recording_file_name(&e.file_name)])
+        .map(|name| manager.sessions_dir.join(name))
+        .filter(|path| path.exists())
+        .collect();
+    let total = files.len() + 1;
+    for (done, path) in files.iter().enumerate() {
+        let data = manager.read_data(path)?;
+        manager.write_data(path, &data)?;
+        progress(done + 1, total);
+    }
+    manager.save_index()?;
+    let clipboard = match &manager.encryptor {
+        Some(encryptor) => crate::clipboard::reseal(workspace, encryptor).map_err(|e| {
+            MemoryError::PersistenceError {
+                message: format!("Failed to re-encrypt clipboard history: {}", e),
+            }
+        })?,
+        None => 0,
+    };
+    progress(total, total);
+
+    finish_key_rotation(store, workspace).map_err(key_unavailable)?;
+    Ok(files.len() + clipboard)
+}
+
+fn key_unavailable(e: EncryptionError) -> MemoryError {
+    MemoryError::SessionKeyUnavailable {
+        message: e.to_string(),
+    }
+}
+
+/// Whether the file at `path` starts like sealed session data.
+fn file_is_sealed(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut header = [0u8; 12];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .is_ok_and(|()| is_sealed(&header))
+}
+
+/// The recording file stored next to a session data file.
+fn recording_file_name(session_file: &str) -> String {
+    let stem = session_file.strip_suffix(".json").unwrap_or(session_file);
+    format!("{}.replay.json", stem)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -755,6 +1579,11 @@ mod tests {
             file_name: "test.json".to_string(),
             tags: vec!["bugfix".to_string()],
             project_type: Some("Rust".to_string()),
+            persona: None,
+            artifacts: Vec::new(),
+            manifest: BTreeMap::new(),
+            task_runs: Vec::new(),
+            branch: None,
         };
         let json = serde_json::to_string(&entry).unwrap();
         let restored: SessionEntry = serde_json::from_str(&json).unwrap();
@@ -790,6 +1619,11 @@ mod tests {
             file_name: format!("{}.json", name),
             tags: tags.into_iter().map(|s| s.to_string()).collect(),
             project_type: None,
+            persona: None,
+            artifacts: Vec::new(),
+            manifest: BTreeMap::new(),
+            task_runs: Vec::new(),
+            branch: None,
         };
         index.entries.push(make_entry(
             "debug-auth",
@@ -855,6 +1689,11 @@ mod tests {
             file_name: format!("{}.json", name),
             tags: tags.into_iter().map(|s| s.to_string()).collect(),
             project_type: None,
+            persona: None,
+            artifacts: Vec::new(),
+            manifest: BTreeMap::new(),
+            task_runs: Vec::new(),
+            branch: None,
         };
         index
             .entries
@@ -874,6 +1713,122 @@ mod tests {
         assert_eq!(results.len(), 0);
     }
 
+    #[test]
+    fn test_set_active_persona_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut mgr = create_test_manager(dir.path());
+
+        let entry = mgr.start_session(Some("ops-session"));
+        mgr.set_active_persona(Some("ops-sre")).unwrap();
+
+        let index = SessionIndex::load(dir.path()).unwrap();
+        let saved = index.find_by_id(entry.id).unwrap();
+        assert_eq!(saved.persona.as_deref(), Some("ops-sre"));
+    }
+
+    #[test]
+    fn test_set_artifacts_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut mgr = create_test_manager(dir.path());
+
+        let entry = mgr.start_session(Some("artifact-session"));
+        let record = crate::artifacts::ArtifactRecord::from_artifact(
+            "pdf_generate",
+            &crate::types::Artifact::FileCreated {
+                path: "report.pdf".into(),
+            },
+            Some(dir.path()),
+            None,
+        )
+        .unwrap();
+        mgr.set_artifacts(std::slice::from_ref(&record)).unwrap();
+        assert_eq!(mgr.active_artifacts(), vec![record.clone()]);
+
+        let index = SessionIndex::load(dir.path()).unwrap();
+        let saved = index.find_by_id(entry.id).unwrap();
+        assert_eq!(saved.artifacts, vec![record]);
+    }
+
+    #[test]
+    fn test_task_runs_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut mgr = create_test_manager(dir.path());
+
+        let entry = mgr.start_session(Some("task-session"));
+        let original = crate::task_checkpoint::TaskRun {
+            task_id: Uuid::new_v4(),
+            continuation_of: None,
+            goal: "Refactor auth".into(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            steps_completed: 6,
+            steps_total: 9,
+            cost_usd: 0.42,
+            success: false,
+        };
+        let continuation = crate::task_checkpoint::TaskRun {
+            task_id: Uuid::new_v4(),
+            continuation_of: Some(original.task_id),
+            steps_completed: 9,
+            success: true,
+            ..original.clone()
+        };
+        mgr.set_task_runs(&[original.clone(), continuation.clone()])
+            .unwrap();
+        assert_eq!(mgr.active_task_runs().len(), 2);
+
+        let index = SessionIndex::load(dir.path()).unwrap();
+        let saved = index.find_by_id(entry.id).unwrap();
+        assert_eq!(saved.task_runs[1].continuation_of, Some(original.task_id));
+        assert_eq!(saved.task_runs, vec![original, continuation]);
+    }
+
+    #[test]
+    fn test_recording_round_trip_and_legacy_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut mgr = create_test_manager(dir.path());
+
+        mgr.start_session(Some("before-replay"));
+        mgr.save_checkpoint(&MemorySystem::new(10), 0).unwrap();
+        let err = mgr.load_recording("before-replay").unwrap_err();
+        assert!(
+            matches!(err, crate::replay::ReplayError::NotRecorded(ref name) if name == "before-replay")
+        );
+
+        mgr.start_session(Some("recorded"));
+        let mut recorder = crate::replay::SessionRecorder::new();
+        recorder.record_turn(
+            Uuid::new_v4(),
+            "say hi",
+            1,
+            "mock",
+            &[Message::system("sys"), Message::user("say hi")],
+            Vec::new(),
+            &crate::types::Content::text("hi"),
+            &crate::types::TokenUsage::default(),
+        );
+        mgr.save_recording(recorder.recording()).unwrap();
+        assert_eq!(mgr.active_recording().unwrap().turns.len(), 1);
+
+        let (entry, recording) = mgr.load_recording("recorded").unwrap();
+        assert_eq!(entry.name, "recorded");
+        assert_eq!(recording.turns[0].task, "say hi");
+
+        mgr.delete_session("recorded").unwrap();
+        assert!(matches!(
+            mgr.load_recording("recorded"),
+            Err(crate::replay::ReplayError::SessionNotFound(_))
+        ));
+        assert_eq!(
+            std::fs::read_dir(dir.path())
+                .unwrap()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name().to_string_lossy().ends_with(".replay.json"))
+                .count(),
+            0
+        );
+    }
+
     #[test]
     fn test_tag_session_case_insensitive_dedup() {
         let dir = tempfile::tempdir().unwrap();
@@ -907,6 +1862,11 @@ mod tests {
             file_name: format!("{}.json", name),
             tags: vec![],
             project_type: None,
+            persona: None,
+            artifacts: Vec::new(),
+            manifest: BTreeMap::new(),
+            task_runs: Vec::new(),
+            branch: None,
         };
         index.entries.push(make_entry("session-1", Some("fix bug")));
 
@@ -935,6 +1895,11 @@ mod tests {
             file_name: format!("{}.json", name),
             tags: tags.into_iter().map(|s| s.to_string()).collect(),
             project_type: None,
+            persona: None,
+            artifacts: Vec::new(),
+            manifest: BTreeMap::new(),
+            task_runs: Vec::new(),
+            branch: None,
         };
         index.entries.push(make_entry("s1", vec!["BugFix"]));
         index.entries.push(make_entry("s2", vec!["bugfix"]));
@@ -1028,6 +1993,11 @@ mod tests {
             file_name: "a.json".to_string(),
             tags: vec![],
             project_type: None,
+            persona: None,
+            artifacts: Vec::new(),
+            manifest: BTreeMap::new(),
+            task_runs: Vec::new(),
+            branch: None,
         });
 
         // Completed session
@@ -1044,6 +2014,11 @@ mod tests {
             file_name: "b.json".to_string(),
             tags: vec![],
             project_type: None,
+            persona: None,
+            artifacts: Vec::new(),
+            manifest: BTreeMap::new(),
+            task_runs: Vec::new(),
+            branch: None,
         });
 
         // Empty session (no messages) — should NOT be included
@@ -1060,6 +2035,11 @@ mod tests {
             file_name: "c.json".to_string(),
             tags: vec![],
             project_type: None,
+            persona: None,
+            artifacts: Vec::new(),
+            manifest: BTreeMap::new(),
+            task_runs: Vec::new(),
+            branch: None,
         });
 
         let mgr = SessionManager::from_index(index);
@@ -1067,4 +2047,279 @@ mod tests {
         assert_eq!(incomplete.len(), 1);
         assert_eq!(incomplete[0].name, "interrupted");
     }
+
+    #[test]
+    fn test_workspace_key_seals_index_and_transcripts() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = crate::credentials::InMemoryCredentialStore::new();
+        let mut mgr = SessionManager::new(dir.path())
+            .unwrap()
+            .with_workspace_key(&store, dir.path())
+            .unwrap();
+        assert!(mgr.is_encrypted());
+        mgr.start_session(Some("client-work"));
+        let mut memory = MemorySystem::new(20);
+        memory.working.current_goal = Some("Fix the ACME invoice bug".into());
+        memory
+            .short_term
+            .add(Message::user("the invoice total is wrong"));
+        mgr.save_checkpoint(&memory, 120).unwrap();
+
+        let sessions_dir = dir.path().join(".rustant").join("sessions");
+        let index_json = std::fs::read_to_string(sessions_dir.join("index.json")).unwrap();
+        assert!(index_json.contains("client-work"));
+        assert!(!index_json.contains("ACME"));
+
+        let reopened = SessionManager::new(dir.path())
+            .unwrap()
+            .with_workspace_key(&store, dir.path())
+            .unwrap();
+        let entry = reopened.list_sessions(1)[0];
+        assert_eq!(entry.last_goal.as_deref(), Some("Fix the ACME invoice bug"));
+        assert_eq!(entry.total_tokens, 120);
+        assert_eq!(reopened.search_transcripts("INVOICE TOTAL").len(), 1);
+        assert!(reopened.search_transcripts("unrelated").is_empty());
+        let export = reopened.export_session("client-work").unwrap();
+        assert_eq!(export["entry"]["name"], "client-work");
+        assert!(export.to_string().contains("the invoice total is wrong"));
+
+        // Without the key, metadata is still readable but transcripts are not.
+        let lost = crate::credentials::InMemoryCredentialStore::new();
+        let err = SessionManager::new(dir.path())
+            .unwrap()
+            .with_workspace_key(&lost, dir.path())
+            .err()
+            .unwrap();
+        assert!(matches!(err, MemoryError::SessionKeyUnavailable { .. }));
+        let index = SessionIndex::load(&sessions_dir).unwrap();
+        assert_eq!(index.entries[0].name, "client-work");
+        assert!(index.entries[0].last_goal.is_none());
+    }
+
+    #[test]
+    fn test_rotate_session_key_reencrypts_and_migrates_plaintext() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = crate::credentials::InMemoryCredentialStore::new();
+
+        // A session saved before encryption was enabled.
+        let mut plain = SessionManager::new(dir.path()).unwrap();
+        plain.start_session(Some("old"));
+        let mut memory = MemorySystem::new(20);
+        memory.short_term.add(Message::user("plaintext transcript"));
+        plain.save_checkpoint(&memory, 10).unwrap();
+        let session_file = plain
+            .sessions_dir()
+            .join(&plain.index().entries[0].file_name);
+        assert!(!file_is_sealed(&session_file));
+
+        let mut steps = Vec::new();
+        let rewritten =
+            rotate_session_key(dir.path(), &store, |done, total| steps.push((done, total)))
+                .unwrap();
+        assert_eq!(rewritten, 1);
+        assert_eq!(steps, vec![(1, 2), (2, 2)]);
+        assert!(file_is_sealed(&session_file));
+        let first_key = load_session_key(&store, dir.path())
+            .unwrap()
+            .unwrap()
+            .key_id();
+
+        rotate_session_key(dir.path(), &store, |_, _| {}).unwrap();
+        let key = load_session_key(&store, dir.path()).unwrap().unwrap();
+        assert_ne!(key.key_id(), first_key);
+        assert!(!key.is_rotating());
+
+        let mut mgr = SessionManager::new(dir.path())
+            .unwrap()
+            .with_workspace_key(&store, dir.path())
+            .unwrap();
+        let (memory, _) = mgr.resume_session("old").unwrap();
+        assert_eq!(memory.short_term.len(), 1);
+    }
+
+    /// A three-turn session one minute per message apart, with a fact and
+    /// an artifact from before turn 2 and another of each after it.
+    fn save_branching_session(mgr: &mut SessionManager, dir: &Path) -> DateTime<Utc> {
+        let start = Utc::now() - chrono::Duration::hours(1);
+        let at = |minutes: i64| start + chrono::Duration::minutes(minutes);
+        let mut memory = MemorySystem::new(20);
+        for (i, text) in [
+            "one",
+            "reply one",
+            "two",
+            "reply two",
+            "three",
+            "reply three",
+        ]
+        .iter()
+        .enumerate()
+        {
+            let mut message = if i % 2 == 0 {
+                Message::user(*text)
+            } else {
+                Message::assistant(*text)
+            };
+            message.timestamp = at(i as i64);
+            memory.add_message(message);
+        }
+        let mut early = crate::memory::Fact::new("uses tokio", "chat");
+        early.created_at = at(1);
+        let mut late = crate::memory::Fact::new("prefers async-std", "chat");
+        late.created_at = at(3);
+        memory.long_term.facts = vec![early, late];
+
+        let artifact = |name: &str, minutes: i64| {
+            std::fs::write(dir.join(name), name).unwrap();
+            let mut record = crate::artifacts::ArtifactRecord::from_artifact(
+                "file_write",
+                &crate::types::Artifact::FileCreated { path: name.into() },
+                Some(dir),
+                None,
+            )
+            .unwrap();
+            record.created_at = at(minutes);
+            record
+        };
+        mgr.start_session(Some("main"));
+        mgr.set_artifacts(&[artifact("plan.md", 1), artifact("impl.rs", 3)])
+            .unwrap();
+        mgr.save_checkpoint(&memory, 500).unwrap();
+        at(2)
+    }
+
+    #[test]
+    fn test_fork_session_truncates_at_turn() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut mgr = create_test_manager(dir.path());
+        let fork_time = save_branching_session(&mut mgr, dir.path());
+
+        for turn in [0, 4] {
+            assert!(mgr.fork_session("main", turn, None).is_err());
+        }
+        let branch = mgr.fork_session("main", 1, None).unwrap();
+        assert_eq!(branch.name, "main-fork-1");
+        assert_eq!(mgr.active_session_id(), Some(branch.id));
+        let point = branch.branch.clone().unwrap();
+        assert_eq!(point.turn, 1);
+        assert_eq!(point.forked_at, fork_time);
+        assert_eq!(branch.artifacts.len(), 1);
+        assert_eq!(branch.manifest.len(), 1);
+
+        // Resuming the branch is an ordinary resume.
+        let mut mgr = create_test_manager(dir.path());
+        let (memory, _) = mgr.resume_session("main-fork-1").unwrap();
+        assert_eq!(memory.short_term.len(), 2);
+        assert_eq!(memory.long_term.facts.len(), 1);
+        assert_eq!(memory.long_term.facts[0].content, "uses tokio");
+        assert_eq!(mgr.active_artifacts()[0].title, branch.artifacts[0].title);
+        let (parent, _) = mgr.resume_session("main").unwrap();
+        assert_eq!(parent.short_term.len(), 6);
+
+        let tree: Vec<(usize, &str)> = mgr
+            .index()
+            .list_tree(10)
+            .into_iter()
+            .map(|(depth, e)| (depth, e.name.as_str()))
+            .collect();
+        assert_eq!(tree, vec![(0, "main"), (1, "main-fork-1")]);
+    }
+
+    #[test]
+    fn test_list_tree_keeps_newest_roots() {
+        let base = Utc::now();
+        let entry = |name: &str, minutes: i64, parent: Option<Uuid>| SessionEntry {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            created_at: base + chrono::Duration::minutes(minutes),
+            updated_at: base + chrono::Duration::minutes(minutes),
+            last_goal: None,
+            summary: None,
+            message_count: 1,
+            total_tokens: 0,
+            completed: false,
+            file_name: format!("{}.json", name),
+            tags: Vec::new(),
+            project_type: None,
+            persona: None,
+            artifacts: Vec::new(),
+            manifest: BTreeMap::new(),
+            task_runs: Vec::new(),
+            branch: parent.map(|parent_id| BranchPoint {
+                parent_id,
+                turn: 1,
+                forked_at: base,
+            }),
+        };
+        let mut index = SessionIndex::default();
+        let oldest = entry("oldest", 0, None);
+        let middle = entry("middle", 1, None);
+        let fork = entry("middle-fork", 2, Some(middle.id));
+        let orphan = entry("orphan", 3, Some(Uuid::new_v4()));
+        index.entries = vec![oldest, middle, fork, orphan, entry("newest", 4, None)];
+
+        let names = |limit| -> Vec<(usize, String)> {
+            index
+                .list_tree(limit)
+                .into_iter()
+                .map(|(depth, e)| (depth, e.name.clone()))
+                .collect()
+        };
+        assert_eq!(
+            names(3),
+            vec![
+                (0, "newest".to_string()),
+                (0, "orphan".to_string()),
+                (0, "middle".to_string()),
+                (1, "middle-fork".to_string()),
+            ]
+        );
+        assert_eq!(names(1), vec![(0, "newest".to_string())]);
+    }
+
+    #[test]
+    fn test_compare_branches_uses_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut mgr = create_test_manager(dir.path());
+        save_branching_session(&mut mgr, dir.path());
+        mgr.fork_session("main", 1, Some("retry")).unwrap();
+
+        // The branch rewrites impl.rs in the shared workspace and adds a test.
+        let mut artifacts = mgr.active_artifacts();
+        for name in ["impl.rs", "test.rs"] {
+            std::fs::write(dir.path().join(name), "rewritten").unwrap();
+            artifacts.push(
+                crate::artifacts::ArtifactRecord::from_artifact(
+                    "file_write",
+                    &crate::types::Artifact::FileCreated { path: name.into() },
+                    Some(dir.path()),
+                    None,
+                )
+                .unwrap(),
+            );
+        }
+        mgr.set_artifacts(&artifacts).unwrap();
+
+        let comparison = mgr.compare_branches("retry", None).unwrap();
+        assert_eq!(comparison.base, "main");
+        assert_eq!(comparison.right, "main");
+        let statuses: Vec<(String, BranchDiff)> = comparison
+            .changes
+            .iter()
+            .map(|c| {
+                let name = Path::new(&c.item).file_name().unwrap();
+                (name.to_string_lossy().into_owned(), c.status)
+            })
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("impl.rs".to_string(), BranchDiff::Differs),
+                ("test.rs".to_string(), BranchDiff::OnlyLeft),
+            ]
+        );
+
+        mgr.start_session(Some("unrelated"));
+        assert!(mgr.compare_branches("retry", Some("unrelated")).is_err());
+        assert!(mgr.compare_branches("main", None).is_err());
+    }
 }
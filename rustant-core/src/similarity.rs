@@ -0,0 +1,634 @@
+//! Duplicate and near-duplicate detection for short texts.
+//!
+//! Memory facts, inbox items and scan findings all need to know whether a new
+//! piece of text says the same as one already stored. [`SimilarityService`]
+//! answers that with a configurable chain of strategies, cheapest first:
+//!
+//! - **Exact** — identical bytes.
+//! - **Normalized** — identical after Unicode compatibility decomposition,
+//!   dropping accents and invisible characters, lowercasing, and treating
+//!   punctuation and whitespace runs as a single separator.
+//! - **Token set** — the same normalized words in any order.
+//! - **MinHash** — estimated Jaccard similarity of the normalized word sets.
+//! - **Embedding** — cosine similarity of embedding vectors, when an embedder
+//!   is attached.
+//!
+//! The first four work on a [`Signature`], which is cheap to compute and small
+//! enough to store next to each item, so hot paths compare signatures without
+//! touching the original text. Near-duplicate strategies never match texts that
+//! mention different numbers: "deploy 41 failed" and "deploy 42 failed" are
+//! different events.
+//!
+//! Each consumer picks its own thresholds ([`SimilarityConfig::facts`],
+//! [`SimilarityConfig::inbox`], [`SimilarityConfig::findings`]). A match comes
+//! back as a [`SimilarityScore`] naming the strategy and score, and
+//! [`SimilarityService::explain`] scores every strategy for debugging.
+
+use crate::search::{SimpleEmbedder, cosine_similarity};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+/// Number of MinHash slots in a signature.
+const MINHASH_SLOTS: usize = 64;
+
+/// A way of deciding that two texts are the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimilarityStrategy {
+    Exact,
+    Normalized,
+    TokenSet,
+    MinHash,
+    Embedding,
+}
+
+impl SimilarityStrategy {
+    /// Whether the strategy tolerates differences short of the same words.
+    fn is_near_duplicate(self) -> bool {
+        matches!(self, Self::MinHash | Self::Embedding)
+    }
+}
+
+impl fmt::Display for SimilarityStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Exact => "exact",
+            Self::Normalized => "normalized",
+            Self::TokenSet => "token_set",
+            Self::MinHash => "minhash",
+            Self::Embedding => "embedding",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Which strategies a consumer uses and how similar near-duplicates must be.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimilarityConfig {
+    /// Strategies tried, in order.
+    pub strategies: Vec<SimilarityStrategy>,
+    /// Minimum estimated Jaccard similarity for a MinHash match.
+    pub minhash_threshold: f32,
+    /// Minimum cosine similarity for an embedding match.
+    pub embedding_threshold: f32,
+}
+
+impl SimilarityConfig {
+    /// Memory facts: conservative, since a wrongly dropped fact is lost.
+    pub fn facts() -> Self {
+        Self {
+            strategies: vec![
+                SimilarityStrategy::Exact,
+                SimilarityStrategy::Normalized,
+                SimilarityStrategy::TokenSet,
+                SimilarityStrategy::MinHash,
+                SimilarityStrategy::Embedding,
+            ],
+            minhash_threshold: 0.85,
+            embedding_threshold: 0.9,
+        }
+    }
+
+    /// Inbox notifications: the same event reworded by each channel that
+    /// reports it. Signature strategies only, to stay cheap on every sync.
+    pub fn inbox() -> Self {
+        Self {
+            strategies: vec![
+                SimilarityStrategy::Exact,
+                SimilarityStrategy::Normalized,
+                SimilarityStrategy::TokenSet,
+                SimilarityStrategy::MinHash,
+            ],
+            minhash_threshold: 0.7,
+            embedding_threshold: 1.0,
+        }
+    }
+
+    /// Scan findings clustered across runs.
+    pub fn findings() -> Self {
+        Self {
+            strategies: vec![
+                SimilarityStrategy::Exact,
+                SimilarityStrategy::Normalized,
+                SimilarityStrategy::TokenSet,
+                SimilarityStrategy::MinHash,
+            ],
+            minhash_threshold: 0.8,
+            embedding_threshold: 1.0,
+        }
+    }
+
+    /// Threshold a strategy's score must reach.
+    pub fn threshold(&self, strategy: SimilarityStrategy) -> f32 {
+        match strategy {
+            SimilarityStrategy::MinHash => self.minhash_threshold,
+            SimilarityStrategy::Embedding => self.embedding_threshold,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Precomputed fingerprints of a text, stored alongside the items they describe.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    exact: u64,
+    normalized: u64,
+    token_set: u64,
+    numbers: u64,
+    #[serde(
+        serialize_with = "serialize_slots",
+        deserialize_with = "deserialize_slots"
+    )]
+    minhash: Vec<u16>,
+}
+
+impl Signature {
+    /// Fingerprint `text`.
+    pub fn of(text: &str) -> Self {
+        let normalized = normalize(text);
+        let mut tokens: Vec<&str> = normalized.split(' ').filter(|t| !t.is_empty()).collect();
+        let mut numbers: Vec<&str> = tokens
+            .iter()
+            .copied()
+            .filter(|t| t.chars().any(char::is_numeric))
+            .collect();
+        numbers.sort_unstable();
+        tokens.sort_unstable();
+        tokens.dedup();
+
+        let minhash = if tokens.is_empty() {
+            Vec::new()
+        } else {
+            let hashes: Vec<u64> = tokens.iter().map(|t| fnv1a(t.as_bytes())).collect();
+            (0..MINHASH_SLOTS)
+                .map(|slot| {
+                    let seed = (slot as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+                    let min = hashes
+                        .iter()
+                        .map(|h| splitmix64(h ^ seed))
+                        .min()
+                        .unwrap_or(u64::MAX);
+                    (min >> 48) as u16
+                })
+                .collect()
+        };
+
+        Self {
+            exact: fnv1a(text.as_bytes()),
+            normalized: fnv1a(normalized.as_bytes()),
+            token_set: fnv1a(tokens.join(" ").as_bytes()),
+            numbers: fnv1a(numbers.join(" ").as_bytes()),
+            minhash,
+        }
+    }
+
+    /// Estimated Jaccard similarity of the two texts' word sets.
+    fn minhash_similarity(&self, other: &Signature) -> f32 {
+        if self.minhash.is_empty() || self.minhash.len() != other.minhash.len() {
+            return 0.0;
+        }
+        let equal = self
+            .minhash
+            .iter()
+            .zip(&other.minhash)
+            .filter(|(a, b)| a == b)
+            .count();
+        equal as f32 / self.minhash.len() as f32
+    }
+}
+
+/// How similar two texts are by one strategy — the answer to "why similar".
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimilarityScore {
+    pub strategy: SimilarityStrategy,
+    pub score: f32,
+    pub threshold: f32,
+    /// The texts mention different numbers, which rules out a near-duplicate.
+    #[serde(default)]
+    pub numbers_differ: bool,
+}
+
+impl SimilarityScore {
+    /// Whether this score makes the texts duplicates.
+    pub fn is_match(&self) -> bool {
+        self.score >= self.threshold && !(self.numbers_differ && self.strategy.is_near_duplicate())
+    }
+}
+
+impl fmt::Display for SimilarityScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:.2} (threshold {:.2})",
+            self.strategy, self.score, self.threshold
+        )?;
+        if self.numbers_differ && self.strategy.is_near_duplicate() {
+            f.write_str(", numbers differ")?;
+        }
+        Ok(())
+    }
+}
+
+/// Produces embedding vectors for the embedding strategy.
+pub trait TextEmbedder: Send + Sync {
+    fn embed(&self, text: &str) -> Vec<f32>;
+}
+
+impl TextEmbedder for SimpleEmbedder {
+    fn embed(&self, text: &str) -> Vec<f32> {
+        SimpleEmbedder::embed(self, text)
+    }
+}
+
+/// Compares texts with a consumer's [`SimilarityConfig`].
+#[derive(Clone)]
+pub struct SimilarityService {
+    config: SimilarityConfig,
+    embedder: Option<Arc<dyn TextEmbedder>>,
+}
+
+impl fmt::Debug for SimilarityService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimilarityService")
+            .field("config", &self.config)
+            .field("embedder", &self.embedder.is_some())
+            .finish()
+    }
+}
+
+impl SimilarityService {
+    pub fn new(config: SimilarityConfig) -> Self {
+        Self {
+            config,
+            embedder: None,
+        }
+    }
+
+    /// Enable the embedding strategy (builder pattern).
+    pub fn with_embedder(mut self, embedder: Arc<dyn TextEmbedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    pub fn config(&self) -> &SimilarityConfig {
+        &self.config
+    }
+
+    /// The first strategy by which `a` and `b` are duplicates, if any.
+    pub fn compare(&self, a: &str, b: &str) -> Option<SimilarityScore> {
+        self.best_match(a, &Signature::of(a), [((), b, &Signature::of(b))])
+            .map(|(_, score)| score)
+    }
+
+    /// Compare precomputed signatures only, skipping the embedding strategy.
+    pub fn compare_signatures(&self, a: &Signature, b: &Signature) -> Option<SimilarityScore> {
+        self.config
+            .strategies
+            .iter()
+            .filter_map(|&strategy| self.signature_score(strategy, a, b))
+            .find(SimilarityScore::is_match)
+    }
+
+    /// Score every configured strategy for `a` and `b`, matched or not.
+    pub fn explain(&self, a: &str, b: &str) -> Vec<SimilarityScore> {
+        let (sig_a, sig_b) = (Signature::of(a), Signature::of(b));
+        self.config
+            .strategies
+            .iter()
+            .filter_map(|&strategy| match strategy {
+                SimilarityStrategy::Embedding => {
+                    let embedder = self.embedder.as_ref()?;
+                    Some(self.embedding_score(&embedder.embed(a), b, &sig_a, &sig_b))
+                }
+                _ => self.signature_score(strategy, &sig_a, &sig_b),
+            })
+            .collect()
+    }
+
+    /// The candidate most similar to `text`, if any is a duplicate of it.
+    ///
+    /// Candidates are `(key, text, signature)`. Signature strategies run
+    /// first; the embedding strategy only embeds candidates they did not match.
+    pub fn best_match<'a, K>(
+        &self,
+        text: &str,
+        signature: &Signature,
+        candidates: impl IntoIterator<Item = (K, &'a str, &'a Signature)>,
+    ) -> Option<(K, SimilarityScore)> {
+        let embeds = self.embedder.is_some()
+            && self
+                .config
+                .strategies
+                .contains(&SimilarityStrategy::Embedding);
+        let mut query_vector: Option<Vec<f32>> = None;
+        let mut best: Option<(K, SimilarityScore)> = None;
+
+        for (key, candidate_text, candidate) in candidates {
+            let mut found = self.compare_signatures(signature, candidate);
+            if found.is_none()
+                && embeds
+                && let Some(embedder) = &self.embedder
+            {
+                let vector = query_vector.get_or_insert_with(|| embedder.embed(text));
+                let score = self.embedding_score(vector, candidate_text, signature, candidate);
+                found = Some(score).filter(SimilarityScore::is_match);
+            }
+            if let Some(score) = found
+                && best.as_ref().is_none_or(|(_, b)| score.score > b.score)
+            {
+                let exact = score.score >= 1.0;
+                best = Some((key, score));
+                if exact {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    /// Group items that are duplicates of each other, directly or through a
+    /// chain of matches. Returns groups of indices, each in input order.
+    pub fn cluster(&self, items: &[(&str, &Signature)]) -> Vec<Vec<usize>> {
+        let mut parent: Vec<usize> = (0..items.len()).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for i in 0..items.len() {
+            for j in (i + 1)..items.len() {
+                let (text, signature) = items[i];
+                let (other_text, other) = items[j];
+                if root(&mut parent, i) != root(&mut parent, j)
+                    && self
+                        .best_match(text, signature, [((), other_text, other)])
+                        .is_some()
+                {
+                    let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                    parent[a.max(b)] = a.min(b);
+                }
+            }
+        }
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut group_of: Vec<Option<usize>> = vec![None; items.len()];
+        for i in 0..items.len() {
+            let r = root(&mut parent, i);
+            match group_of[r] {
+                Some(g) => groups[g].push(i),
+                None => {
+                    group_of[r] = Some(groups.len());
+                    groups.push(vec![i]);
+                }
+            }
+        }
+        groups
+    }
+
+    fn signature_score(
+        &self,
+        strategy: SimilarityStrategy,
+        a: &Signature,
+        b: &Signature,
+    ) -> Option<SimilarityScore> {
+        let same = |equal: bool| if equal { 1.0 } else { 0.0 };
+        let score = match strategy {
+            SimilarityStrategy::Exact => same(a.exact == b.exact),
+            SimilarityStrategy::Normalized => same(a.normalized == b.normalized),
+            SimilarityStrategy::TokenSet => same(a.token_set == b.token_set),
+            SimilarityStrategy::MinHash => a.minhash_similarity(b),
+            SimilarityStrategy::Embedding => return None,
+        };
+        Some(SimilarityScore {
+            strategy,
+            score,
+            threshold: self.config.threshold(strategy),
+            numbers_differ: a.numbers != b.numbers,
+        })
+    }
+
+    fn embedding_score(
+        &self,
+        vector: &[f32],
+        other_text: &str,
+        a: &Signature,
+        b: &Signature,
+    ) -> SimilarityScore {
+        let score = self
+            .embedder
+            .as_ref()
+            .map(|e| cosine_similarity(vector, &e.embed(other_text)))
+            .unwrap_or(0.0);
+        SimilarityScore {
+            strategy: SimilarityStrategy::Embedding,
+            score,
+            threshold: self.config.embedding_threshold,
+            numbers_differ: a.numbers != b.numbers,
+        }
+    }
+}
+
+/// Canonical form of `text` used by the normalized, token-set and MinHash
+/// strategies: compatibility-decomposed, without accents or invisible
+/// characters, lowercased, with words separated by single spaces.
+pub fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut separate = false;
+    for c in text.nfkd() {
+        for c in c.to_lowercase() {
+            if is_combining_mark(c) || is_invisible(c) {
+                continue;
+            }
+            if c.is_alphanumeric() {
+                if separate && !out.is_empty() {
+                    out.push(' ');
+                }
+                separate = false;
+                out.push(c);
+            } else {
+                separate = true;
+            }
+        }
+    }
+    out
+}
+
+/// Zero-width and formatting characters that should not split words.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}' | '\u{200B}'..='\u{200F}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}'
+    )
+}
+
+/// 64-bit FNV-1a, stable across runs so stored signatures stay comparable.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+fn serialize_slots<S: Serializer>(slots: &[u16], serializer: S) -> Result<S::Ok, S::Error> {
+    let hex: String = slots.iter().map(|s| format!("{:04x}", s)).collect();
+    serializer.serialize_str(&hex)
+}
+
+fn deserialize_slots<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u16>, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    if hex.len() % 4 != 0 || !hex.is_ascii() {
+        return Err(serde::de::Error::custom("invalid minhash slots"));
+    }
+    (0..hex.len())
+        .step_by(4)
+        .map(|i| u16::from_str_radix(&hex[i..i + 4], 16).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facts() -> SimilarityService {
+        SimilarityService::new(SimilarityConfig::facts())
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("  Café—au   LAIT!\n"), "cafe au lait");
+        assert_eq!(normalize("ﬁle\u{200B}name"), "filename");
+        assert_eq!(normalize("..."), "");
+    }
+
+    #[test]
+    fn test_strategies_in_order() {
+        let service = facts();
+        let exact = service.compare("Uses tokio", "Uses tokio").unwrap();
+        assert_eq!(exact.strategy, SimilarityStrategy::Exact);
+        let normalized = service.compare("Uses tokio.", "uses   Tokio").unwrap();
+        assert_eq!(normalized.strategy, SimilarityStrategy::Normalized);
+        let reordered = service
+            .compare(
+                "Tests run with cargo nextest",
+                "Run tests with cargo nextest",
+            )
+            .unwrap();
+        assert_eq!(reordered.strategy, SimilarityStrategy::TokenSet);
+        assert!(
+            service
+                .compare("Project uses PostgreSQL", "Frontend uses React")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_numbers_block_near_duplicates() {
+        let service = SimilarityService::new(SimilarityConfig::inbox());
+        let a = "Deploy 41 of the billing api failed on staging cluster east";
+        let b = "Deploy 42 of the billing api failed on staging cluster east";
+        assert!(service.compare(a, b).is_none());
+        let minhash = service
+            .explain(a, b)
+            .into_iter()
+            .find(|s| s.strategy == SimilarityStrategy::MinHash)
+            .unwrap();
+        assert!(minhash.numbers_differ);
+        assert!(minhash.to_string().contains("numbers differ"));
+    }
+
+    #[test]
+    fn test_minhash_catches_reworded_notification() {
+        let service = SimilarityService::new(SimilarityConfig::inbox());
+        let score = service
+            .compare(
+                "[Slack] Build failed on main: integration tests timed out in payments service",
+                "Build failed on main - integration tests timed out in payments service",
+            )
+            .unwrap();
+        assert_eq!(score.strategy, SimilarityStrategy::MinHash);
+        assert!(score.score >= 0.7);
+    }
+
+    #[test]
+    fn test_embedding_strategy_needs_embedder() {
+        let a = "Docs use British spelling, the user prefers";
+        let b = "The user prefers British spelling in docs";
+        let mut config = SimilarityConfig::facts();
+        config.embedding_threshold = 0.8;
+        assert!(
+            SimilarityService::new(config.clone())
+                .compare(a, b)
+                .is_none()
+        );
+        let service =
+            SimilarityService::new(config).with_embedder(Arc::new(SimpleEmbedder::new(256)));
+        let score = service.compare(a, b).unwrap();
+        assert_eq!(score.strategy, SimilarityStrategy::Embedding);
+        assert!(
+            service
+                .explain(a, b)
+                .iter()
+                .any(|s| s.strategy == SimilarityStrategy::Embedding && s.is_match())
+        );
+    }
+
+    #[test]
+    fn test_best_match_prefers_strongest() {
+        let service = SimilarityService::new(SimilarityConfig::inbox());
+        let texts = [
+            "Build failed on main: integration tests timed out in payments",
+            "build failed on main integration tests timed out in payments",
+        ];
+        let signatures: Vec<Signature> = texts.iter().map(|t| Signature::of(t)).collect();
+        let query = "Build failed on main: integration tests timed out in payments";
+        let (index, score) = service
+            .best_match(
+                query,
+                &Signature::of(query),
+                texts
+                    .iter()
+                    .zip(&signatures)
+                    .enumerate()
+                    .map(|(i, (t, s))| (i, *t, s)),
+            )
+            .unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(score.strategy, SimilarityStrategy::Exact);
+    }
+
+    #[test]
+    fn test_cluster() {
+        let service = SimilarityService::new(SimilarityConfig::findings());
+        let texts = [
+            "SQL injection in src/db.rs:42 via user_id",
+            "Hardcoded secret in config/app.toml",
+            "sql injection in SRC/db.rs:42 via user_id",
+            "SQL injection in src/db.rs:42 via user_id.",
+        ];
+        let signatures: Vec<Signature> = texts.iter().map(|t| Signature::of(t)).collect();
+        let items: Vec<(&str, &Signature)> = texts.iter().copied().zip(&signatures).collect();
+        assert_eq!(service.cluster(&items), vec![vec![0, 2, 3], vec![1]]);
+    }
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let signature = Signature::of("Refund requested for order 1234");
+        let json = serde_json::to_string(&signature).unwrap();
+        let back: Signature = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, signature);
+        assert!(
+            serde_json::from_str::<Signature>(&json.replace("\"minhash\":\"", "\"minhash\":\"zz"))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,874 @@
+//! Skill installation with provenance.
+//!
+//! `rustant skill install <source>` fetches a skill from a local path, a git
+//! repository or an HTTPS URL, checks its minisign signature against
+//! `[skills] trusted_keys`, and reviews it before it is copied into the
+//! skills directory. Every install is recorded in [`LOCK_FILE`] next to the
+//! installed files, so `list` can show where a skill came from and whether
+//! its source moved on, and `update` can re-fetch it from the same place.
+//!
+//! A source resolves to one skill file: the path itself, `SKILL.md` at the
+//! root of a directory, repository or `.tar.gz` bundle, or the body of a
+//! plain URL. Its signature is the sibling `SKILL.md.minisig` (or
+//! `<url>.minisig`) and covers the skill file's bytes.
+
+use super::parser::{ParseError, parse_skill_md};
+use super::types::{SkillDefinition, SkillRiskLevel};
+use super::validator::{ValidationError, validate_skill};
+use crate::config::ApprovalMode;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Lockfile recording the provenance of installed skills.
+pub const LOCK_FILE: &str = "skills.lock.json";
+
+/// Skill file looked up in directories, repositories and bundles.
+const SKILL_FILE: &str = "SKILL.md";
+
+/// Largest skill file accepted.
+const MAX_SKILL_BYTES: usize = 1024 * 1024;
+
+/// Skill installation settings (`[skills]`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillsConfig {
+    /// Directory holding installed skills; defaults to `skills/` in the data directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+    /// Minisign public keys whose signatures mark a skill as trusted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_keys: Vec<String>,
+}
+
+impl SkillsConfig {
+    /// The configured skills directory, or [`default_skills_dir`].
+    pub fn skills_dir(&self) -> PathBuf {
+        self.dir.clone().unwrap_or_else(default_skills_dir)
+    }
+}
+
+/// `skills/` under the rustant data directory.
+pub fn default_skills_dir() -> PathBuf {
+    directories::ProjectDirs::from("dev", "rustant", "rustant")
+        .map(|d| d.data_dir().join("skills"))
+        .unwrap_or_else(|| PathBuf::from(".rustant/skills"))
+}
+
+/// Errors raised while fetching or installing a skill.
+#[derive(Debug, thiserror::Error)]
+pub enum InstallError {
+    #[error("failed to fetch skill from {location}: {message}")]
+    Fetch { location: String, message: String },
+    #[error("no SKILL.md found in {0}")]
+    NoSkillFile(String),
+    #[error("skill file is larger than {MAX_SKILL_BYTES} bytes")]
+    TooLarge,
+    #[error("skill file is not valid: {0}")]
+    Parse(#[from] ParseError),
+    #[error("plain http is not accepted for skills; use https: {0}")]
+    InsecureUrl(String),
+    #[error("skill signature is invalid: {0}")]
+    BadSignature(String),
+    #[error("skill '{0}' is not installed from a recorded source")]
+    NotInstalled(String),
+    #[error("skill name '{0}' cannot be used as a file name")]
+    InvalidName(String),
+    #[error("skill I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("skill lockfile is invalid: {0}")]
+    Lock(#[from] serde_json::Error),
+}
+
+/// Where a skill is fetched from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SkillSource {
+    /// A SKILL.md file, or a directory containing one.
+    Local { path: PathBuf },
+    /// A git repository with SKILL.md at its root, at a branch or tag.
+    Git {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reference: Option<String>,
+    },
+    /// An HTTPS URL to a SKILL.md file or a `.tar.gz` bundle.
+    Https { url: String },
+}
+
+impl SkillSource {
+    /// Interpret an install argument.
+    ///
+    /// `git@…`, `ssh://…`, `git://…`, `git+https://…`, and `https://` URLs
+    /// ending in `.git`, naming a `#branch-or-tag`, or pointing at a GitHub,
+    /// GitLab or Codeberg repository root are git sources. Other `https://`
+    /// URLs are bundles, and anything else is a local path.
+    pub fn parse(arg: &str) -> Result<Self, InstallError> {
+        let arg = arg.trim();
+        let (location, reference) = match arg.split_once('#') {
+            Some((location, reference)) if !reference.is_empty() => {
+                (location, Some(reference.to_string()))
+            }
+            _ => (arg, None),
+        };
+        if location.starts_with("git@")
+            || location.starts_with("ssh://")
+            || location.starts_with("git://")
+        {
+            return Ok(SkillSource::Git {
+                url: location.to_string(),
+                reference,
+            });
+        }
+        if let Some(url) = location.strip_prefix("git+") {
+            return Ok(SkillSource::Git {
+                url: url.to_string(),
+                reference,
+            });
+        }
+        if location.starts_with("http://") {
+            return Err(InstallError::InsecureUrl(arg.to_string()));
+        }
+        if let Some(rest) = location.strip_prefix("https://") {
+            let segments: Vec<&str> = rest.trim_end_matches('/').split('/').collect();
+            let forge = matches!(segments[0], "github.com" | "gitlab.com" | "codeberg.org");
+            if location.ends_with(".git") || reference.is_some() || (forge && segments.len() == 3) {
+                return Ok(SkillSource::Git {
+                    url: location.to_string(),
+                    reference,
+                });
+            }
+            return Ok(SkillSource::Https {
+                url: location.to_string(),
+            });
+        }
+        Ok(SkillSource::Local {
+            path: PathBuf::from(arg),
+        })
+    }
+
+    /// Whether the skill comes from somewhere other than this machine.
+    pub fn is_remote(&self) -> bool {
+        !matches!(self, SkillSource::Local { .. })
+    }
+}
+
+impl fmt::Display for SkillSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkillSource::Local { path } => write!(f, "{}", path.display()),
+            SkillSource::Git {
+                url,
+                reference: Some(reference),
+            } => write!(f, "{}#{}", url, reference),
+            SkillSource::Git { url, .. } | SkillSource::Https { url } => write!(f, "{}", url),
+        }
+    }
+}
+
+/// Outcome of checking a skill's minisign signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SignatureStatus {
+    /// Signed by a key in `[skills] trusted_keys`.
+    Verified { key_id: String },
+    /// Signed by a key that is not trusted; the signature was not checked.
+    UntrustedKey { key_id: String },
+    /// No signature next to the skill.
+    Unsigned,
+}
+
+impl SignatureStatus {
+    pub fn is_verified(&self) -> bool {
+        matches!(self, SignatureStatus::Verified { .. })
+    }
+}
+
+impl fmt::Display for SignatureStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureStatus::Verified { key_id } => write!(f, "signed by trusted key {}", key_id),
+            SignatureStatus::UntrustedKey { key_id } => {
+                write!(f, "signed by untrusted key {}", key_id)
+            }
+            SignatureStatus::Unsigned => write!(f, "unsigned"),
+        }
+    }
+}
+
+/// Where an installed skill came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Provenance {
+    pub source: SkillSource,
+    /// Commit the skill was fetched at, for git sources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// SHA-256 of the skill file.
+    pub sha256: String,
+    pub signature: SignatureStatus,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A fetched and parsed skill awaiting review.
+#[derive(Debug, Clone)]
+pub struct FetchedSkill {
+    pub skill: SkillDefinition,
+    pub content: String,
+    pub provenance: Provenance,
+}
+
+/// Raw skill file, its signature, and the commit it came from.
+struct Fetched {
+    content: String,
+    signature: Option<String>,
+    commit: Option<String>,
+}
+
+/// Fetch, parse and signature-check the skill at `source`.
+///
+/// A signature from a trusted key that does not verify is an error; a
+/// missing signature or one from an unknown key is reported in the
+/// provenance instead.
+pub async fn fetch(
+    source: &SkillSource,
+    trusted_keys: &[String],
+) -> Result<FetchedSkill, InstallError> {
+    let fetched = fetch_raw(source).await?;
+    let skill = parse_skill_md(&fetched.content)?;
+    let signature = check_signature(
+        fetched.content.as_bytes(),
+        fetched.signature.as_deref(),
+        trusted_keys,
+    )?;
+    Ok(FetchedSkill {
+        skill,
+        provenance: Provenance {
+            source: source.clone(),
+            commit: fetched.commit,
+            sha256: sha256_hex(&fetched.content),
+            signature,
+            fetched_at: Utc::now(),
+        },
+        content: fetched.content,
+    })
+}
+
+async fn fetch_raw(source: &SkillSource) -> Result<Fetched, InstallError> {
+    let fetched = match source {
+        SkillSource::Local { path } => read_local(path)?,
+        SkillSource::Git { url, reference } => fetch_git(url, reference.as_deref()).await?,
+        SkillSource::Https { url } => fetch_https(url).await?,
+    };
+    if fetched.content.len() > MAX_SKILL_BYTES {
+        return Err(InstallError::TooLarge);
+    }
+    Ok(fetched)
+}
+
+fn read_local(path: &Path) -> Result<Fetched, InstallError> {
+    let file = if path.is_dir() {
+        path.join(SKILL_FILE)
+    } else {
+        path.to_path_buf()
+    };
+    if !file.is_file() {
+        return Err(InstallError::NoSkillFile(path.display().to_string()));
+    }
+    let mut signature_path = file.clone().into_os_string();
+    signature_path.push(".minisig");
+    Ok(Fetched {
+        content: std::fs::read_to_string(&file)?,
+        signature: std::fs::read_to_string(PathBuf::from(signature_path)).ok(),
+        commit: None,
+    })
+}
+
+async fn git(args: &[&str], location: &str) -> Result<String, InstallError> {
+    let output = tokio::process::Command::new("git")
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .await
+        .map_err(|e| InstallError::Fetch {
+            location: location.to_string(),
+            message: format!("could not run git: {}", e),
+        })?;
+    if !output.status.success() {
+        return Err(InstallError::Fetch {
+            location: location.to_string(),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+async fn fetch_git(url: &str, reference: Option<&str>) -> Result<Fetched, InstallError> {
+    let checkout = std::env::temp_dir().join(format!("rustant-skill-{}", uuid::Uuid::new_v4()));
+    let dir = checkout.to_string_lossy().into_owned();
+    let result = async {
+        let mut args = vec!["clone", "--depth", "1", "--quiet"];
+        if let Some(reference) = reference {
+            args.extend(["--branch", reference]);
+        }
+        args.extend(["--", url, dir.as_str()]);
+        git(&args, url).await?;
+        let commit = git(&["-C", &dir, "rev-parse", "HEAD"], url).await?;
+        let mut fetched = read_local(&checkout).map_err(|e| match e {
+            InstallError::NoSkillFile(_) => InstallError::NoSkillFile(url.to_string()),
+            other => other,
+        })?;
+        fetched.commit = Some(commit);
+        Ok(fetched)
+    }
+    .await;
+    let _ = std::fs::remove_dir_all(&checkout);
+    result
+}
+
+async fn fetch_https(url: &str) -> Result<Fetched, InstallError> {
+    let fetch_error = |message: String| InstallError::Fetch {
+        location: url.to_string(),
+        message,
+    };
+    let client = reqwest::Client::builder()
+        .user_agent(format!("rustant/{}", crate::updater::CURRENT_VERSION))
+        .build()
+        .map_err(|e| fetch_error(e.to_string()))?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| fetch_error(e.to_string()))?;
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| fetch_error(e.to_string()))?;
+
+    if url.ends_with(".tar.gz") || url.ends_with(".tgz") {
+        return read_bundle(&body[..]).map_err(|e| match e {
+            InstallError::NoSkillFile(_) => InstallError::NoSkillFile(url.to_string()),
+            other => other,
+        });
+    }
+    let content = String::from_utf8(body.to_vec())
+        .map_err(|_| fetch_error("response is not UTF-8 text".into()))?;
+    let signature = match client.get(format!("{}.minisig", url)).send().await {
+        Ok(r) if r.status().is_success() => r.text().await.ok(),
+        _ => None,
+    };
+    Ok(Fetched {
+        content,
+        signature,
+        commit: None,
+    })
+}
+
+/// Read SKILL.md and its signature from a `.tar.gz` bundle, at the root or
+/// inside a single top-level directory.
+fn read_bundle(bytes: &[u8]) -> Result<Fetched, InstallError> {
+    use std::io::Read;
+
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(bytes));
+    let mut content: Option<(usize, String)> = None;
+    let mut signatures: Vec<(usize, String)> = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let depth = path.components().count();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if depth > 2 || !entry.header().entry_type().is_file() {
+            continue;
+        }
+        if entry.size() as usize > MAX_SKILL_BYTES {
+            return Err(InstallError::TooLarge);
+        }
+        let mut text = String::new();
+        if name == SKILL_FILE {
+            entry.read_to_string(&mut text)?;
+            if content.as_ref().is_none_or(|(d, _)| depth < *d) {
+                content = Some((depth, text));
+            }
+        } else if name == "SKILL.md.minisig" {
+            entry.read_to_string(&mut text)?;
+            signatures.push((depth, text));
+        }
+    }
+    let (depth, content) = content.ok_or_else(|| InstallError::NoSkillFile(String::new()))?;
+    Ok(Fetched {
+        content,
+        signature: signatures
+            .into_iter()
+            .find(|(d, _)| *d == depth)
+            .map(|(_, s)| s),
+        commit: None,
+    })
+}
+
+fn sha256_hex(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Key id of a minisign public key or signature, as minisign prints it.
+fn minisign_key_id(text: &str) -> Option<String> {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with("untrusted comment:"))?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(line)
+        .ok()?;
+    let id: [u8; 8] = bytes.get(2..10)?.try_into().ok()?;
+    Some(format!("{:016X}", u64::from_le_bytes(id)))
+}
+
+fn check_signature(
+    content: &[u8],
+    signature: Option<&str>,
+    trusted_keys: &[String],
+) -> Result<SignatureStatus, InstallError> {
+    let Some(signature) = signature else {
+        return Ok(SignatureStatus::Unsigned);
+    };
+    let key_id = minisign_key_id(signature)
+        .ok_or_else(|| InstallError::BadSignature("malformed minisign signature".into()))?;
+    let Some(key) = trusted_keys
+        .iter()
+        .find(|k| minisign_key_id(k).as_deref() == Some(key_id.as_str()))
+    else {
+        return Ok(SignatureStatus::UntrustedKey { key_id });
+    };
+    crate::updater::verify_minisign(key, signature, content)
+        .map_err(|e| InstallError::BadSignature(e.to_string()))?;
+    Ok(SignatureStatus::Verified { key_id })
+}
+
+/// Security review of a skill shown before it is installed.
+#[derive(Debug, Clone)]
+pub struct SkillReview {
+    pub risk_level: SkillRiskLevel,
+    /// Problems that stop the install.
+    pub blocking: Vec<String>,
+    /// Findings shown before the user confirms.
+    pub warnings: Vec<String>,
+    /// Tools whose description or body reads like a prompt injection.
+    pub suspicious: Vec<String>,
+}
+
+/// Run [`validate_skill`] and the injection detector over a skill.
+///
+/// Missing tools block the install; missing secrets only need setting
+/// before the skill is used.
+pub fn review(
+    skill: &SkillDefinition,
+    available_tools: &[String],
+    available_secrets: &[String],
+) -> SkillReview {
+    let validation = validate_skill(skill, available_tools, available_secrets);
+    let mut warnings = validation.warnings;
+    let mut blocking = Vec::new();
+    for error in validation.errors {
+        match error {
+            ValidationError::MissingSecret(name) => {
+                warnings.push(format!("Needs secret {} before use", name))
+            }
+            other => blocking.push(other.to_string()),
+        }
+    }
+
+    let detector = crate::injection::InjectionDetector::new();
+    let suspicious = skill
+        .tools
+        .iter()
+        .filter_map(|tool| {
+            let scan = detector.scan_tool_output(&format!("{}\n{}", tool.description, tool.body));
+            scan.is_suspicious.then(|| {
+                let kinds: Vec<String> = scan
+                    .detected_patterns
+                    .iter()
+                    .map(|p| format!("{:?}", p.pattern_type))
+                    .collect();
+                format!("Tool '{}': {}", tool.name, kinds.join(", "))
+            })
+        })
+        .collect();
+
+    SkillReview {
+        risk_level: validation.risk_level,
+        blocking,
+        warnings,
+        suspicious,
+    }
+}
+
+/// Why installing needs a second, typed confirmation, if it does.
+///
+/// Suspicious tool bodies always do. In every approval mode but `yolo`, so
+/// does a remote skill not signed by a trusted key, and an update that
+/// drops a signature the installed version had.
+pub fn extra_confirmation(
+    provenance: &Provenance,
+    review: &SkillReview,
+    mode: ApprovalMode,
+    installed: Option<&Provenance>,
+) -> Option<String> {
+    if !review.suspicious.is_empty() {
+        return Some("tool text reads like a prompt injection".into());
+    }
+    if mode == ApprovalMode::Yolo || provenance.signature.is_verified() {
+        return None;
+    }
+    if installed.is_some_and(|p| p.signature.is_verified()) {
+        return Some("the installed version was signed by a trusted key; this one is not".into());
+    }
+    provenance
+        .source
+        .is_remote()
+        .then(|| format!("remote skill is {}", provenance.signature))
+}
+
+/// One lockfile entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockedSkill {
+    /// File name inside the skills directory.
+    pub file: String,
+    pub version: String,
+    pub provenance: Provenance,
+    pub installed_at: DateTime<Utc>,
+}
+
+/// Provenance of installed skills, keyed by skill name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SkillLock {
+    #[serde(default)]
+    pub skills: BTreeMap<String, LockedSkill>,
+}
+
+impl SkillLock {
+    /// Load the lockfile in `dir`; a missing file is an empty lock.
+    pub fn load(dir: &Path) -> Result<Self, InstallError> {
+        match std::fs::read_to_string(dir.join(LOCK_FILE)) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), InstallError> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(LOCK_FILE);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+/// File name a skill is installed under.
+fn skill_file_name(name: &str) -> Result<String, InstallError> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(InstallError::InvalidName(name.to_string()));
+    }
+    Ok(format!("{}.md", name))
+}
+
+/// Write a reviewed skill into `dir` and record its provenance.
+pub fn install(dir: &Path, fetched: &FetchedSkill) -> Result<PathBuf, InstallError> {
+    let file = skill_file_name(&fetched.skill.name)?;
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(&file);
+    let tmp = dir.join(format!(".{}.tmp", file));
+    std::fs::write(&tmp, &fetched.content)?;
+    std::fs::rename(&tmp, &path)?;
+
+    let mut lock = SkillLock::load(dir)?;
+    lock.skills.insert(
+        fetched.skill.name.clone(),
+        LockedSkill {
+            file,
+            version: fetched.skill.version.clone(),
+            provenance: fetched.provenance.clone(),
+            installed_at: Utc::now(),
+        },
+    );
+    lock.save(dir)?;
+    Ok(path)
+}
+
+/// Whether a skill's source has changed since it was installed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateStatus {
+    UpToDate,
+    /// The source moved on; the new commit or content hash.
+    Available(String),
+    /// The source could not be checked.
+    Unknown(String),
+}
+
+/// Compare an installed skill with its source. Git sources are checked
+/// with `git ls-remote`; others are re-read and hashed.
+pub async fn check_for_update(locked: &LockedSkill) -> UpdateStatus {
+    let provenance = &locked.provenance;
+    if let SkillSource::Git { url, reference } = &provenance.source {
+        let reference = reference.as_deref().unwrap_or("HEAD");
+        return match git(&["ls-remote", "--", url, reference], url).await {
+            Ok(out) => match remote_commit(&out) {
+                Some(commit) if Some(&commit) == provenance.commit.as_ref() => {
+                    UpdateStatus::UpToDate
+                }
+                Some(commit) => UpdateStatus::Available(commit),
+                None => UpdateStatus::Unknown(format!("{} not found", reference)),
+            },
+            Err(e) => UpdateStatus::Unknown(e.to_string()),
+        };
+    }
+    match fetch_raw(&provenance.source).await {
+        Ok(fetched) => {
+            let sha256 = sha256_hex(&fetched.content);
+            if sha256 == provenance.sha256 {
+                UpdateStatus::UpToDate
+            } else {
+                UpdateStatus::Available(sha256)
+            }
+        }
+        Err(e) => UpdateStatus::Unknown(e.to_string()),
+    }
+}
+
+/// Commit from `git ls-remote` output, preferring a peeled tag.
+fn remote_commit(ls_remote: &str) -> Option<String> {
+    let refs: Vec<(&str, &str)> = ls_remote
+        .lines()
+        .filter_map(|l| l.split_once('\t'))
+        .collect();
+    refs.iter()
+        .find(|(_, name)| name.ends_with("^{}"))
+        .or_else(|| refs.first())
+        .map(|(commit, _)| commit.to_string())
+}
+
+/// What changed between the installed skill and a re-fetched one.
+pub fn describe_changes(old: &SkillDefinition, new: &SkillDefinition) -> Vec<String> {
+    let mut changes = Vec::new();
+    if old.version != new.version {
+        changes.push(format!("version {} → {}", old.version, new.version));
+    }
+    if old.description != new.description {
+        changes.push("description changed".to_string());
+    }
+    for tool in &new.tools {
+        match old.tools.iter().find(|t| t.name == tool.name) {
+            None => changes.push(format!("+ tool {}", tool.name)),
+            Some(before) => {
+                let mut parts = Vec::new();
+                if before.description != tool.description {
+                    parts.push("description");
+                }
+                if before.parameters != tool.parameters {
+                    parts.push("parameters");
+                }
+                if before.body != tool.body {
+                    parts.push("body");
+                }
+                if !parts.is_empty() {
+                    changes.push(format!(
+                        "~ tool {}: {} changed",
+                        tool.name,
+                        parts.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+    for tool in &old.tools {
+        if !new.tools.iter().any(|t| t.name == tool.name) {
+            changes.push(format!("- tool {}", tool.name));
+        }
+    }
+    let requirement = |r: &super::types::SkillRequirement| format!("{} {}", r.req_type, r.name);
+    let old_requires: Vec<String> = old.requires.iter().map(requirement).collect();
+    let new_requires: Vec<String> = new.requires.iter().map(requirement).collect();
+    for r in new_requires.iter().filter(|r| !old_requires.contains(r)) {
+        changes.push(format!("+ requires {}", r));
+    }
+    for r in old_requires.iter().filter(|r| !new_requires.contains(r)) {
+        changes.push(format!("- requires {}", r));
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKILL: &str = "---\nname: weather\nversion: 1.0.0\ndescription: Forecasts\nrequires:\n  - type: tool\n    name: web_fetch\n---\n\n## Tools\n\n### forecast\n\nGet the forecast.\n\n**Body:**\n```text\nweb_fetch the forecast for {city}\n```\n";
+
+    /// A minisign key pair as (public key file, sign function).
+    fn signer() -> (String, impl Fn(&[u8]) -> String) {
+        use ring::signature::{Ed25519KeyPair, KeyPair};
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let key_id: [u8; 8] = rand::random();
+        let b64 = |b: &[u8]| base64::engine::general_purpose::STANDARD.encode(b);
+
+        let mut pk = b"Ed".to_vec();
+        pk.extend_from_slice(&key_id);
+        pk.extend_from_slice(pair.public_key().as_ref());
+        let public = format!("untrusted comment: minisign public key\n{}\n", b64(&pk));
+
+        let sign = move |data: &[u8]| {
+            let sig = pair.sign(data);
+            let mut blob = b"Ed".to_vec();
+            blob.extend_from_slice(&key_id);
+            blob.extend_from_slice(sig.as_ref());
+            let comment = "file:SKILL.md";
+            let mut global = sig.as_ref().to_vec();
+            global.extend_from_slice(comment.as_bytes());
+            format!(
+                "untrusted comment: signature\n{}\ntrusted comment: {}\n{}\n",
+                b64(&blob),
+                comment,
+                b64(pair.sign(&global).as_ref())
+            )
+        };
+        (public, sign)
+    }
+
+    #[test]
+    fn test_parse_sources() {
+        assert_eq!(
+            SkillSource::parse("./skills/weather").unwrap(),
+            SkillSource::Local {
+                path: "./skills/weather".into()
+            }
+        );
+        assert_eq!(
+            SkillSource::parse("https://github.com/acme/weather-skill#v1.2").unwrap(),
+            SkillSource::Git {
+                url: "https://github.com/acme/weather-skill".into(),
+                reference: Some("v1.2".into()),
+            }
+        );
+        assert!(matches!(
+            SkillSource::parse("git@github.com:acme/weather.git").unwrap(),
+            SkillSource::Git { .. }
+        ));
+        assert_eq!(
+            SkillSource::parse("https://example.com/skills/weather.tar.gz").unwrap(),
+            SkillSource::Https {
+                url: "https://example.com/skills/weather.tar.gz".into()
+            }
+        );
+        assert!(matches!(
+            SkillSource::parse("http://example.com/SKILL.md"),
+            Err(InstallError::InsecureUrl(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_signed_local_skill_installs_with_provenance() {
+        let src = tempfile::TempDir::new().unwrap();
+        let (public, sign) = signer();
+        std::fs::write(src.path().join("SKILL.md"), SKILL).unwrap();
+        std::fs::write(src.path().join("SKILL.md.minisig"), sign(SKILL.as_bytes())).unwrap();
+
+        let source = SkillSource::parse(src.path().to_str().unwrap()).unwrap();
+        let fetched = fetch(&source, std::slice::from_ref(&public)).await.unwrap();
+        assert_eq!(fetched.skill.name, "weather");
+        assert!(fetched.provenance.signature.is_verified());
+
+        let untrusted = fetch(&source, &[]).await.unwrap();
+        assert!(matches!(
+            untrusted.provenance.signature,
+            SignatureStatus::UntrustedKey { .. }
+        ));
+
+        let skills = tempfile::TempDir::new().unwrap();
+        let path = install(skills.path(), &fetched).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), SKILL);
+        let lock = SkillLock::load(skills.path()).unwrap();
+        let locked = &lock.skills["weather"];
+        assert_eq!(locked.file, "weather.md");
+        assert_eq!(locked.provenance.sha256, fetched.provenance.sha256);
+        assert_eq!(check_for_update(locked).await, UpdateStatus::UpToDate);
+
+        let edited = SKILL.replace("1.0.0", "1.1.0");
+        std::fs::write(src.path().join("SKILL.md"), &edited).unwrap();
+        assert!(matches!(
+            check_for_update(locked).await,
+            UpdateStatus::Available(_)
+        ));
+        // The old signature no longer matches the edited file.
+        assert!(matches!(
+            fetch(&source, &[public]).await,
+            Err(InstallError::BadSignature(_))
+        ));
+    }
+
+    #[test]
+    fn test_review_and_extra_confirmation() {
+        let skill = parse_skill_md(SKILL).unwrap();
+        let blocked = review(&skill, &[], &[]);
+        assert_eq!(blocked.blocking, vec!["Missing required tool: web_fetch"]);
+
+        let ok = review(&skill, &["web_fetch".to_string()], &[]);
+        assert!(ok.blocking.is_empty());
+        assert!(ok.suspicious.is_empty());
+
+        let mut provenance = Provenance {
+            source: SkillSource::Https {
+                url: "https://example.com/SKILL.md".into(),
+            },
+            commit: None,
+            sha256: sha256_hex(SKILL),
+            signature: SignatureStatus::Unsigned,
+            fetched_at: Utc::now(),
+        };
+        assert!(extra_confirmation(&provenance, &ok, ApprovalMode::Cautious, None).is_some());
+        assert!(extra_confirmation(&provenance, &ok, ApprovalMode::Paranoid, None).is_some());
+        assert!(extra_confirmation(&provenance, &ok, ApprovalMode::Yolo, None).is_none());
+
+        provenance.source = SkillSource::Local {
+            path: "SKILL.md".into(),
+        };
+        assert!(extra_confirmation(&provenance, &ok, ApprovalMode::Paranoid, None).is_none());
+    }
+
+    #[test]
+    fn test_describe_changes() {
+        let old = parse_skill_md(SKILL).unwrap();
+        let new_text = SKILL
+            .replace("1.0.0", "1.1.0")
+            .replace("web_fetch the forecast", "shell_exec curl the forecast")
+            + "\n### alerts\n\nWeather alerts.\n";
+        let new = parse_skill_md(&new_text).unwrap();
+        assert_eq!(
+            describe_changes(&old, &new),
+            vec![
+                "version 1.0.0 → 1.1.0",
+                "~ tool forecast: body changed",
+                "+ tool alerts",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remote_commit_prefers_peeled_tag() {
+        let out = "aaa\trefs/tags/v1.2\nbbb\trefs/tags/v1.2^{}";
+        assert_eq!(remote_commit(out).as_deref(), Some("bbb"));
+        assert_eq!(remote_commit("ccc\tHEAD").as_deref(), Some("ccc"));
+        assert_eq!(remote_commit(""), None);
+    }
+}
@@ -4,13 +4,20 @@
 //! Skills define tool registrations via YAML frontmatter and markdown-based
 //! tool definitions with parameter schemas and body templates.
 
+pub mod install;
 pub mod parser;
 pub mod types;
 pub mod validator;
 
+pub use install::{
+    FetchedSkill, InstallError, LockedSkill, Provenance, SignatureStatus, SkillLock, SkillReview,
+    SkillSource, SkillsConfig, UpdateStatus,
+};
 pub use parser::{ParseError, parse_skill_md};
 pub use types::{SkillConfig, SkillDefinition, SkillRequirement, SkillRiskLevel, SkillToolDef};
-pub use validator::{ValidationError, ValidationResult, validate_skill};
+pub use validator::{
+    ValidationError, ValidationResult, validate_skill, validate_skill_with_aliases,
+};
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
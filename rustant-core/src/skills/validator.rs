@@ -1,9 +1,11 @@
 //! Skill security validation.
 //!
 //! Validates skill definitions for security risks: checks required secrets exist,
-//! tool dependencies resolve, and scans for dangerous patterns.
+//! tool dependencies resolve (through aliases of renamed tools), and scans for
+//! dangerous patterns.
 
 use super::types::{SkillDefinition, SkillRiskLevel};
+use crate::tool_aliases::AliasTable;
 
 /// Errors from security validation.
 #[derive(Debug, thiserror::Error)]
@@ -12,6 +14,8 @@ pub enum ValidationError {
     MissingSecret(String),
     #[error("Missing required tool: {0}")]
     MissingTool(String),
+    #[error("Required tool removed: {0}")]
+    RemovedTool(String),
     #[error("Dangerous pattern detected: {0}")]
     DangerousPattern(String),
 }
@@ -44,15 +48,40 @@ pub fn validate_skill(
     skill: &SkillDefinition,
     available_tools: &[String],
     available_secrets: &[String],
+) -> ValidationResult {
+    validate_skill_with_aliases(
+        skill,
+        available_tools,
+        available_secrets,
+        &AliasTable::builtin(),
+    )
+}
+
+/// Validate a skill definition, resolving required tools through `aliases`.
+/// Requirements naming a deprecated alias are satisfied by the renamed tool
+/// and produce a warning; ones naming a removed alias are errors.
+pub fn validate_skill_with_aliases(
+    skill: &SkillDefinition,
+    available_tools: &[String],
+    available_secrets: &[String],
+    aliases: &AliasTable,
 ) -> ValidationResult {
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
     let mut max_risk = SkillRiskLevel::Low;
 
     // Check required tools
-    for req in &skill.requires {
-        if req.req_type == "tool" && !available_tools.contains(&req.name) {
-            errors.push(ValidationError::MissingTool(req.name.clone()));
+    for req in skill.requires.iter().filter(|r| r.req_type == "tool") {
+        match aliases.check_reference(&req.name) {
+            Ok((canonical, warning)) => {
+                if let Some(warning) = warning {
+                    warnings.push(format!("Requirement: {}", warning));
+                }
+                if !available_tools.contains(&canonical) && !available_tools.contains(&req.name) {
+                    errors.push(ValidationError::MissingTool(req.name.clone()));
+                }
+            }
+            Err(err) => errors.push(ValidationError::RemovedTool(err.to_string())),
         }
     }
 
@@ -207,6 +236,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_renamed_tool_requirement() {
+        let skill = make_skill(
+            "test",
+            vec![SkillRequirement {
+                req_type: "tool".into(),
+                name: "old_tool".into(),
+            }],
+            vec![],
+        );
+        let mut aliases = AliasTable::new("1.5.0");
+        aliases.insert(
+            crate::tool_aliases::ToolAlias::new("old_tool", "new_tool", "1.0.0")
+                .removed_in("2.0.0"),
+        );
+
+        let result = validate_skill_with_aliases(&skill, &["new_tool".into()], &[], &aliases);
+        assert!(result.is_valid);
+        assert!(result.warnings[0].contains("use 'new_tool'"));
+
+        let mut removed = AliasTable::new("2.0.0");
+        removed.insert(aliases.get("old_tool").unwrap().clone());
+        let result = validate_skill_with_aliases(&skill, &["new_tool".into()], &[], &removed);
+        assert!(!result.is_valid);
+        assert!(
+            result.errors[0]
+                .to_string()
+                .contains("use 'new_tool' instead")
+        );
+    }
+
     #[test]
     fn test_validate_dangerous_shell_exec() {
         let skill = make_skill(
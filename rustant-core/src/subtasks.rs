@@ -0,0 +1,890 @@
+//! Sub-task decomposition: independent units of work run concurrently.
+//!
+//! With `[plan] decomposition = true`, the planner may return a list of
+//! independent sub-tasks alongside its steps (e.g. one per crate for "update the
+//! copyright header in every crate"). Each sub-task declares the tools it may
+//! use, the paths it expects to write, and a tool-call budget.
+//!
+//! [`schedule`] decides which sub-tasks may run in parallel: those using only
+//! read-only tools, or whose writes go to declared path sets disjoint from every
+//! other parallel sub-task. Everything else runs one at a time afterwards.
+//!
+//! Declarations can be wrong, so [`PathClaims`] also tracks which files each
+//! running sub-task actually touches. A sub-task that touches a file another
+//! sub-task is writing (or writes a file another is reading) waits until that
+//! sub-task finishes, so the two are serialized rather than racing. A wait that
+//! would deadlock is refused and reported to the sub-task's model instead.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Notify, mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+
+use crate::agent::{AgentCallback, RegisteredTool};
+use crate::brain::LlmProvider;
+use crate::contracts::{ContractSet, ContractViolation};
+use crate::error::{LlmError, ToolError};
+use crate::safety::{ActionRequest, ApprovalDecision};
+use crate::types::{
+    CompletionRequest, Content, Message, RiskLevel, TokenUsage, ToolDefinition, ToolOutput,
+};
+
+/// System prompt for a sub-task's own Think → Act loop.
+pub const SUBTASK_SYSTEM_PROMPT: &str = "You are completing one sub-task of a larger goal. \
+Other sub-tasks run at the same time, so do only the work described below, using only the \
+tools provided. When the sub-task is done, reply with a short summary of what you did and \
+any problems you hit.";
+
+/// Planner instructions appended when decomposition is enabled.
+pub const DECOMPOSITION_PROMPT: &str = r#"
+Sub-task decomposition:
+When the task splits into independent pieces of the same kind (e.g. one per file, crate, or
+directory), you may add a top-level "subtasks" array. Sub-tasks run concurrently, before the
+steps, so they must not depend on each other.
+{
+  "subtasks": [
+    {
+      "description": "Self-contained instruction for this piece of work",
+      "tools": ["file_read", "file_patch"],
+      "paths": ["crates/foo/src/lib.rs"],
+      "max_tool_calls": 6
+    }
+  ]
+}
+- "tools" lists every tool the sub-task may call; it cannot call anything else
+- "paths" lists every file or directory the sub-task will write; sub-tasks that write
+  should use disjoint paths so they can run in parallel
+- Use "steps" for work that must follow the sub-tasks (e.g. verifying the result)
+"#;
+
+/// An independent unit of work emitted by the planner.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubTask {
+    /// Self-contained instruction for the sub-task's model.
+    pub description: String,
+    /// Tools the sub-task may call.
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Files or directories the sub-task expects to write.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Tool-call budget; `None` uses `plan.subtask_tool_budget`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tool_calls: Option<usize>,
+}
+
+/// Outcome of a sub-task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubTaskStatus {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl std::fmt::Display for SubTaskStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubTaskStatus::Completed => write!(f, "completed"),
+            SubTaskStatus::Failed => write!(f, "failed"),
+            SubTaskStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// The result of one sub-task, reported in `TaskResult::subtasks`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubTaskResult {
+    /// Index of the sub-task in the plan.
+    pub index: usize,
+    pub description: String,
+    pub status: SubTaskStatus,
+    /// Final reply from the sub-task's model (empty on failure).
+    pub output: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Tool calls made.
+    pub tool_calls: usize,
+    /// Whether the sub-task was scheduled in the parallel batch.
+    pub parallel: bool,
+    /// Why the sub-task was run sequentially instead, if it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequential_reason: Option<String>,
+    /// Files on which the sub-task had to wait for another sub-task.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub serialized_on: Vec<PathBuf>,
+    pub usage: TokenUsage,
+}
+
+impl SubTaskResult {
+    pub fn succeeded(&self) -> bool {
+        self.status == SubTaskStatus::Completed
+    }
+
+    /// Whether the failure came from the provider limiter rather than the work itself.
+    pub(crate) fn saturated(&self) -> bool {
+        self.error
+            .as_deref()
+            .is_some_and(LlmError::is_saturation_message)
+    }
+}
+
+/// One-line-per-sub-task summary for the conversation and task response.
+pub fn summarize_results(results: &[SubTaskResult]) -> String {
+    let ok = results.iter().filter(|r| r.succeeded()).count();
+    let mut out = format!("{}/{} sub-tasks succeeded", ok, results.len());
+    for r in results {
+        let icon = if r.succeeded() { "✓" } else { "✗" };
+        let detail = match (&r.error, r.output.is_empty()) {
+            (Some(e), _) => e.clone(),
+            (None, false) => r.output.lines().next().unwrap_or_default().to_string(),
+            (None, true) => r.status.to_string(),
+        };
+        out.push_str(&format!(
+            "\n  {} {}. {} — {}",
+            icon,
+            r.index + 1,
+            r.description,
+            detail
+        ));
+        if !r.serialized_on.is_empty() {
+            let paths: Vec<String> = r
+                .serialized_on
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect();
+            out.push_str(&format!(" (waited on {})", paths.join(", ")));
+        }
+    }
+    out
+}
+
+/// Which sub-tasks run in the parallel batch and which run one at a time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubTaskSchedule {
+    pub parallel: Vec<usize>,
+    /// Sub-tasks that must run sequentially, with the reason.
+    pub sequential: Vec<(usize, String)>,
+}
+
+/// Resolve a path against the workspace and normalize `.`/`..` lexically.
+pub fn normalize_path(workspace: &Path, path: &str) -> PathBuf {
+    let joined = if Path::new(path).is_absolute() {
+        PathBuf::from(path)
+    } else {
+        workspace.join(path)
+    };
+    let mut out = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn paths_overlap(a: &Path, b: &Path) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
+
+/// Decide which sub-tasks are safe to run concurrently.
+///
+/// `risk_of` returns the risk level of a registered tool (`None` if unknown).
+pub fn schedule(
+    subtasks: &[SubTask],
+    risk_of: impl Fn(&str) -> Option<RiskLevel>,
+    workspace: &Path,
+) -> SubTaskSchedule {
+    let mut plan = SubTaskSchedule::default();
+    let mut claimed: Vec<(usize, Vec<PathBuf>)> = Vec::new();
+
+    'subtasks: for (i, subtask) in subtasks.iter().enumerate() {
+        let mut writes = false;
+        for tool in &subtask.tools {
+            match risk_of(tool) {
+                None => {
+                    plan.sequential
+                        .push((i, format!("unknown tool '{}'", tool)));
+                    continue 'subtasks;
+                }
+                Some(RiskLevel::ReadOnly) => {}
+                Some(RiskLevel::Write) => writes = true,
+                Some(risk) => {
+                    plan.sequential
+                        .push((i, format!("uses {} tool '{}'", risk, tool)));
+                    continue 'subtasks;
+                }
+            }
+        }
+        if !writes {
+            plan.parallel.push(i);
+            continue;
+        }
+        if subtask.paths.is_empty() {
+            plan.sequential
+                .push((i, "writes without declaring paths".to_string()));
+            continue;
+        }
+        let paths: Vec<PathBuf> = subtask
+            .paths
+            .iter()
+            .map(|p| normalize_path(workspace, p))
+            .collect();
+        for (other, other_paths) in &claimed {
+            if let Some(p) = paths
+                .iter()
+                .find(|p| other_paths.iter().any(|o| paths_overlap(p, o)))
+            {
+                let shown = p.strip_prefix(workspace).unwrap_or(p);
+                plan.sequential.push((
+                    i,
+                    format!(
+                        "writes {} which overlaps sub-task {}",
+                        shown.display(),
+                        other + 1
+                    ),
+                ));
+                continue 'subtasks;
+            }
+        }
+        claimed.push((i, paths));
+        plan.parallel.push(i);
+    }
+    plan
+}
+
+/// Paths a tool call touches, with whether the access is a write.
+///
+/// Reads the conventional `path`, `file_path`, `file`, and `paths` arguments;
+/// any tool that is not read-only is treated as writing them.
+pub fn touched_paths(
+    arguments: &serde_json::Value,
+    risk: RiskLevel,
+    workspace: &Path,
+) -> Vec<(PathBuf, bool)> {
+    let write = risk != RiskLevel::ReadOnly;
+    let mut paths = Vec::new();
+    for key in ["path", "file_path", "file"] {
+        if let Some(p) = arguments.get(key).and_then(|v| v.as_str()) {
+            paths.push(p.to_string());
+        }
+    }
+    if let Some(list) = arguments.get("paths").and_then(|v| v.as_array()) {
+        paths.extend(list.iter().filter_map(|v| v.as_str().map(String::from)));
+    }
+    let mut normalized: Vec<PathBuf> = paths.iter().map(|p| normalize_path(workspace, p)).collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized.into_iter().map(|p| (p, write)).collect()
+}
+
+/// A file touched at runtime that another sub-task holds, where waiting would deadlock.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "{} is in use by sub-task {} which is waiting on this sub-task; finish without it or report the conflict",
+    path.display(),
+    holder + 1
+)]
+pub struct ClaimConflict {
+    pub path: PathBuf,
+    pub holder: usize,
+}
+
+#[derive(Debug, Default)]
+struct Claim {
+    writer: Option<usize>,
+    readers: BTreeSet<usize>,
+}
+
+#[derive(Debug, Default)]
+struct ClaimState {
+    claims: HashMap<PathBuf, Claim>,
+    /// Sub-tasks currently waiting, and whom they wait on.
+    waiting: HashMap<usize, BTreeSet<usize>>,
+}
+
+impl ClaimState {
+    fn blockers(&self, owner: usize, path: &Path, write: bool) -> BTreeSet<usize> {
+        let Some(claim) = self.claims.get(path) else {
+            return BTreeSet::new();
+        };
+        let mut blockers: BTreeSet<usize> = claim.writer.into_iter().collect();
+        if write {
+            blockers.extend(claim.readers.iter().copied());
+        }
+        blockers.remove(&owner);
+        blockers
+    }
+
+    /// Whether `from` (transitively) waits on `target`.
+    fn waits_on(&self, from: usize, target: usize) -> bool {
+        let mut stack = vec![from];
+        let mut seen = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if id == target {
+                return true;
+            }
+            if seen.insert(id)
+                && let Some(next) = self.waiting.get(&id)
+            {
+                stack.extend(next.iter().copied());
+            }
+        }
+        false
+    }
+}
+
+/// Runtime record of which files each running sub-task has touched.
+///
+/// Reads share a file; a write excludes everyone else. Claims last until the
+/// sub-task finishes, so a later sub-task touching the same file runs after it.
+#[derive(Debug, Default)]
+pub struct PathClaims {
+    state: Mutex<ClaimState>,
+    released: Notify,
+}
+
+impl PathClaims {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Claim `path` for `owner`, waiting for conflicting sub-tasks to finish.
+    ///
+    /// Returns the sub-tasks that had to finish first (empty if none).
+    pub async fn acquire(
+        &self,
+        owner: usize,
+        path: &Path,
+        write: bool,
+    ) -> Result<Vec<usize>, ClaimConflict> {
+        let mut waited_on = BTreeSet::new();
+        loop {
+            let released = self.released.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                let blockers = state.blockers(owner, path, write);
+                if blockers.is_empty() {
+                    state.waiting.remove(&owner);
+                    let claim = state.claims.entry(path.to_path_buf()).or_default();
+                    if write {
+                        claim.writer = Some(owner);
+                        claim.readers.remove(&owner);
+                    } else if claim.writer != Some(owner) {
+                        claim.readers.insert(owner);
+                    }
+                    return Ok(waited_on.into_iter().collect());
+                }
+                if let Some(&holder) = blockers.iter().find(|&&b| state.waits_on(b, owner)) {
+                    state.waiting.remove(&owner);
+                    return Err(ClaimConflict {
+                        path: path.to_path_buf(),
+                        holder,
+                    });
+                }
+                waited_on.extend(blockers.iter().copied());
+                state.waiting.insert(owner, blockers);
+            }
+            released.await;
+        }
+    }
+
+    /// Drop every claim held by `owner` and wake waiting sub-tasks.
+    pub fn release_all(&self, owner: usize) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.claims.retain(|_, claim| {
+                if claim.writer == Some(owner) {
+                    claim.writer = None;
+                }
+                claim.readers.remove(&owner);
+                claim.writer.is_some() || !claim.readers.is_empty()
+            });
+            state.waiting.remove(&owner);
+        }
+        self.released.notify_waiters();
+    }
+}
+
+/// How the agent answered a sub-task's request to run a tool.
+pub(crate) enum SubTaskGate {
+    Proceed,
+    Denied(String),
+    ContractViolation(ContractViolation),
+    NeedsApproval(Box<ActionRequest>),
+}
+
+/// Requests a running sub-task sends to the agent, which owns the safety guardian.
+pub(crate) enum SubTaskRequest {
+    /// Check persona, permissions, and contracts before a tool call.
+    Gate {
+        tool: String,
+        arguments: serde_json::Value,
+        reply: oneshot::Sender<SubTaskGate>,
+    },
+    /// The user's answer to an approval prompt raised by a sub-task.
+    Approval {
+        tool: String,
+        decision: ApprovalDecision,
+    },
+    /// A tool's output failed a contract postcondition and was withheld.
+    ContractViolation {
+        tool: String,
+        violation: ContractViolation,
+    },
+    /// A tool call finished.
+    Executed {
+        tool: String,
+        success: bool,
+        duration_ms: u64,
+        output: Option<ToolOutput>,
+    },
+}
+
+/// Shared state handed to every sub-task runner.
+#[derive(Clone)]
+pub(crate) struct SubTaskEnv {
+    pub provider: Arc<dyn LlmProvider>,
+    pub callback: Arc<dyn AgentCallback>,
+    pub tools: HashMap<String, Arc<RegisteredTool>>,
+    pub claims: Arc<PathClaims>,
+    /// Held while a sub-task shows an approval prompt, so prompts don't interleave.
+    pub approval_lock: Arc<tokio::sync::Mutex<()>>,
+    pub requests: mpsc::UnboundedSender<SubTaskRequest>,
+    pub cancel: CancellationToken,
+    pub workspace: PathBuf,
+    /// Tool contracts, for postconditions checked where the tool runs.
+    pub contracts: Arc<ContractSet>,
+    pub goal: String,
+    pub default_budget: usize,
+}
+
+struct SubTaskRun<'a> {
+    env: &'a SubTaskEnv,
+    index: usize,
+    subtask: &'a SubTask,
+    serialized_on: Vec<PathBuf>,
+}
+
+impl SubTaskRun<'_> {
+    async fn gate(&self, tool: &str, arguments: &serde_json::Value) -> SubTaskGate {
+        let (reply, rx) = oneshot::channel();
+        let sent = self.env.requests.send(SubTaskRequest::Gate {
+            tool: tool.to_string(),
+            arguments: arguments.clone(),
+            reply,
+        });
+        if sent.is_err() {
+            return SubTaskGate::Denied("agent is no longer accepting sub-task requests".into());
+        }
+        rx.await
+            .unwrap_or_else(|_| SubTaskGate::Denied("agent dropped the request".into()))
+    }
+
+    async fn call_tool(
+        &mut self,
+        name: &str,
+        arguments: &serde_json::Value,
+    ) -> Result<ToolOutput, ToolError> {
+        let denied = |reason: String| ToolError::PermissionDenied {
+            name: name.to_string(),
+            reason,
+        };
+        if !self.subtask.tools.iter().any(|t| t == name) {
+            return Err(denied("not among this sub-task's tools".into()));
+        }
+        let tool = self
+            .env
+            .tools
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ToolError::NotFound {
+                name: name.to_string(),
+            })?;
+
+        match self.gate(name, arguments).await {
+            SubTaskGate::Proceed => {}
+            SubTaskGate::Denied(reason) => return Err(denied(reason)),
+            SubTaskGate::ContractViolation(violation) => {
+                return Err(ToolError::ContractViolation {
+                    name: name.to_string(),
+                    violation: Box::new(violation),
+                });
+            }
+            SubTaskGate::NeedsApproval(_) => {
+                // Only this sub-task waits; others keep running.
+                let _prompt = self.env.approval_lock.lock().await;
+                // An earlier prompt may have allowlisted the tool; ask again.
+                match self.gate(name, arguments).await {
+                    SubTaskGate::Proceed => {}
+                    SubTaskGate::Denied(reason) => return Err(denied(reason)),
+                    SubTaskGate::ContractViolation(violation) => {
+                        return Err(ToolError::ContractViolation {
+                            name: name.to_string(),
+                            violation: Box::new(violation),
+                        });
+                    }
+                    SubTaskGate::NeedsApproval(action) => {
+                        let decision = self.env.callback.request_approval(&action).await;
+                        let _ = self.env.requests.send(SubTaskRequest::Approval {
+                            tool: name.to_string(),
+                            decision,
+                        });
+                        if let ApprovalDecision::TimedOut { .. } = decision {
+                            return Err(denied(
+                                "Approval timed out with no decision; the step was skipped".into(),
+                            ));
+                        }
+                        if !decision.is_approved() {
+                            return Err(denied("User rejected the action".into()));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (path, write) in touched_paths(arguments, tool.risk_level, &self.env.workspace) {
+            let waited = self
+                .env
+                .claims
+                .acquire(self.index, &path, write)
+                .await
+                .map_err(|e| denied(e.to_string()))?;
+            if !waited.is_empty() && !self.serialized_on.contains(&path) {
+                self.serialized_on.push(path);
+            }
+        }
+
+        self.env.callback.on_tool_start(name, arguments).await;
+        let summary = crate::tool_call::call_summary(
+            name,
+            &crate::tool_call::summarize_args(arguments),
+            Some(&self.env.workspace),
+        );
+        self.env.callback.on_tool_call_started(name, &summary).await;
+        let start = std::time::Instant::now();
+        let span = tracing::info_span!(
+            "tool_call",
+            tool_name = name,
+            call = %summary,
+            subtask = self.index,
+            outcome = tracing::field::Empty,
+        );
+        let mut result =
+            tracing::Instrument::instrument((tool.executor)(arguments.clone()), span.clone()).await;
+        span.record("outcome", if result.is_ok() { "success" } else { "error" });
+        crate::metrics::record_tool_call(name, result.is_ok(), start.elapsed());
+        let duration_ms = start.elapsed().as_millis() as u64;
+        if let Ok(output) = &result
+            && let Err(violation) = self.env.contracts.check_post(name, output)
+        {
+            let _ = self.env.requests.send(SubTaskRequest::ContractViolation {
+                tool: name.to_string(),
+                violation: violation.clone(),
+            });
+            result = Err(ToolError::ContractViolation {
+                name: name.to_string(),
+                violation: Box::new(violation),
+            });
+        }
+        let shown = match &result {
+            Ok(output) => output.clone(),
+            Err(e) => ToolOutput::error(e.to_string()),
+        };
+        self.env
+            .callback
+            .on_tool_result(name, &shown, duration_ms)
+            .await;
+        let _ = self.env.requests.send(SubTaskRequest::Executed {
+            tool: name.to_string(),
+            success: result.is_ok(),
+            duration_ms,
+            output: result.as_ref().ok().cloned(),
+        });
+        result
+    }
+
+    async fn run(&mut self, result: &mut SubTaskResult) -> Result<String, String> {
+        let budget = self
+            .subtask
+            .max_tool_calls
+            .unwrap_or(self.env.default_budget)
+            .clamp(1, self.env.default_budget.max(1));
+        let definitions: Vec<ToolDefinition> = self
+            .subtask
+            .tools
+            .iter()
+            .filter_map(|name| self.env.tools.get(name))
+            .map(|tool| tool.definition.clone())
+            .collect();
+        let mut messages = vec![
+            Message::system(format!(
+                "{}\n\nOverall goal: {}",
+                SUBTASK_SYSTEM_PROMPT, self.env.goal
+            )),
+            Message::user(&self.subtask.description),
+        ];
+
+        loop {
+            if self.env.cancel.is_cancelled() {
+                return Err("cancelled".into());
+            }
+            let request = CompletionRequest {
+                messages: messages.clone(),
+                tools: (!definitions.is_empty()).then(|| definitions.clone()),
+                ..Default::default()
+            };
+            let response = tokio::select! {
+                response = self.env.provider.complete(request) => response,
+                _ = self.env.cancel.cancelled() => return Err("cancelled".into()),
+            };
+            let response = response.map_err(|e: LlmError| e.to_string())?;
+            result.usage.accumulate(&response.usage);
+
+            let parts = match &response.message.content {
+                Content::MultiPart { parts } => parts.clone(),
+                other => vec![other.clone()],
+            };
+            let calls: Vec<(String, String, serde_json::Value)> = parts
+                .iter()
+                .filter_map(|part| match part {
+                    Content::ToolCall {
+                        id,
+                        name,
+                        arguments,
+                    } => Some((id.clone(), name.clone(), arguments.clone())),
+                    _ => None,
+                })
+                .collect();
+            let text: Vec<&str> = parts.iter().filter_map(Content::as_text).collect();
+            messages.push(response.message.clone());
+            if calls.is_empty() {
+                return Ok(text.join("\n"));
+            }
+
+            for (id, name, arguments) in calls {
+                if result.tool_calls >= budget {
+                    return Err(format!("exceeded its budget of {} tool calls", budget));
+                }
+                result.tool_calls += 1;
+                let message = match self.call_tool(&name, &arguments).await {
+                    Ok(output) => Message::tool_result(&id, &output.content, false),
+                    Err(e) => Message::tool_result(&id, format!("Error: {}", e), true),
+                };
+                messages.push(message);
+            }
+        }
+    }
+}
+
+/// Run one sub-task to completion, releasing its file claims afterwards.
+pub(crate) async fn run_subtask(
+    env: SubTaskEnv,
+    index: usize,
+    subtask: SubTask,
+    parallel: bool,
+    sequential_reason: Option<String>,
+) -> SubTaskResult {
+    let mut result = SubTaskResult {
+        index,
+        description: subtask.description.clone(),
+        status: SubTaskStatus::Failed,
+        output: String::new(),
+        error: None,
+        tool_calls: 0,
+        parallel,
+        sequential_reason,
+        serialized_on: Vec::new(),
+        usage: TokenUsage::default(),
+    };
+    let mut run = SubTaskRun {
+        env: &env,
+        index,
+        subtask: &subtask,
+        serialized_on: Vec::new(),
+    };
+    let outcome = run.run(&mut result).await;
+    result.serialized_on = std::mem::take(&mut run.serialized_on);
+    env.claims.release_all(index);
+    match outcome {
+        Ok(output) => {
+            result.status = SubTaskStatus::Completed;
+            result.output = output;
+        }
+        Err(e) if env.cancel.is_cancelled() => {
+            result.status = SubTaskStatus::Cancelled;
+            result.error = Some(e);
+        }
+        Err(e) => result.error = Some(e),
+    }
+    result
+}
+
+/// Parse the planner's optional `subtasks` array.
+pub fn parse_subtasks(value: &serde_json::Value) -> Vec<SubTask> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| serde_json::from_value::<SubTask>(item.clone()).ok())
+                .filter(|s| !s.description.trim().is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn subtask(tools: &[&str], paths: &[&str]) -> SubTask {
+        SubTask {
+            description: "work".into(),
+            tools: tools.iter().map(|s| s.to_string()).collect(),
+            paths: paths.iter().map(|s| s.to_string()).collect(),
+            max_tool_calls: None,
+        }
+    }
+
+    fn risk(tool: &str) -> Option<RiskLevel> {
+        match tool {
+            "file_read" | "file_list" => Some(RiskLevel::ReadOnly),
+            "file_write" | "file_patch" => Some(RiskLevel::Write),
+            "shell_exec" => Some(RiskLevel::Execute),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_schedule_parallel_and_sequential() {
+        let ws = Path::new("/ws");
+        let subtasks = vec![
+            subtask(&["file_read"], &[]),
+            subtask(&["file_read", "file_patch"], &["crates/a/src/lib.rs"]),
+            subtask(&["file_patch"], &["crates/b"]),
+            subtask(&["file_patch"], &["./crates/b/src/lib.rs"]),
+            subtask(&["file_write"], &[]),
+            subtask(&["shell_exec"], &[]),
+            subtask(&["mystery"], &[]),
+        ];
+        let plan = schedule(&subtasks, risk, ws);
+        assert_eq!(plan.parallel, vec![0, 1, 2]);
+        let reasons: HashMap<usize, String> = plan.sequential.into_iter().collect();
+        assert!(reasons[&3].contains("overlaps sub-task 3"));
+        assert!(reasons[&3].contains("crates/b/src/lib.rs"));
+        assert_eq!(reasons[&4], "writes without declaring paths");
+        assert_eq!(reasons[&5], "uses execute tool 'shell_exec'");
+        assert_eq!(reasons[&6], "unknown tool 'mystery'");
+    }
+
+    #[test]
+    fn test_touched_paths_normalized() {
+        let ws = Path::new("/ws");
+        let args = serde_json::json!({"path": "src/../Cargo.toml", "paths": ["a.rs", "/abs/b.rs"]});
+        let touched = touched_paths(&args, RiskLevel::Write, ws);
+        assert_eq!(
+            touched,
+            vec![
+                (PathBuf::from("/abs/b.rs"), true),
+                (PathBuf::from("/ws/Cargo.toml"), true),
+                (PathBuf::from("/ws/a.rs"), true),
+            ]
+        );
+        assert!(!touched_paths(&args, RiskLevel::ReadOnly, ws)[0].1);
+    }
+
+    #[tokio::test]
+    async fn test_claims_share_reads_and_serialize_writes() {
+        let claims = Arc::new(PathClaims::new());
+        let file = Path::new("/ws/lib.rs");
+        assert!(claims.acquire(0, file, false).await.unwrap().is_empty());
+        assert!(claims.acquire(1, file, false).await.unwrap().is_empty());
+
+        // A write must wait for both readers to finish.
+        let writer = {
+            let claims = Arc::clone(&claims);
+            tokio::spawn(async move { claims.acquire(2, Path::new("/ws/lib.rs"), true).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!writer.is_finished());
+        claims.release_all(0);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!writer.is_finished());
+        claims.release_all(1);
+        let waited = tokio::time::timeout(Duration::from_secs(1), writer)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(waited, vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_claims_refuse_deadlock() {
+        let claims = Arc::new(PathClaims::new());
+        let a = Path::new("/ws/a.rs");
+        let b = Path::new("/ws/b.rs");
+        claims.acquire(0, a, true).await.unwrap();
+        claims.acquire(1, b, true).await.unwrap();
+
+        let first = {
+            let claims = Arc::clone(&claims);
+            tokio::spawn(async move { claims.acquire(0, Path::new("/ws/b.rs"), true).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        // Sub-task 1 waiting on 0 (which waits on 1) would deadlock.
+        let err = claims.acquire(1, a, false).await.unwrap_err();
+        assert_eq!(err.holder, 0);
+        claims.release_all(1);
+        let waited = first.await.unwrap().unwrap();
+        assert_eq!(waited, vec![1]);
+    }
+
+    #[test]
+    fn test_parse_subtasks() {
+        let value = serde_json::json!([
+            {"description": "Update header in crate a", "tools": ["file_patch"], "paths": ["a/src/lib.rs"], "max_tool_calls": 3},
+            {"description": "", "tools": []},
+            "not an object"
+        ]);
+        let subtasks = parse_subtasks(&value);
+        assert_eq!(subtasks.len(), 1);
+        assert_eq!(subtasks[0].max_tool_calls, Some(3));
+        assert_eq!(subtasks[0].paths, vec!["a/src/lib.rs"]);
+    }
+
+    #[test]
+    fn test_summarize_results() {
+        let ok = SubTaskResult {
+            index: 0,
+            description: "Crate a".into(),
+            status: SubTaskStatus::Completed,
+            output: "Updated header\nmore".into(),
+            error: None,
+            tool_calls: 2,
+            parallel: true,
+            sequential_reason: None,
+            serialized_on: vec![PathBuf::from("/ws/NOTICE")],
+            usage: TokenUsage::default(),
+        };
+        let mut failed = ok.clone();
+        failed.index = 1;
+        failed.description = "Crate b".into();
+        failed.status = SubTaskStatus::Failed;
+        failed.error = Some("exceeded its budget of 2 tool calls".into());
+        failed.serialized_on.clear();
+
+        let summary = summarize_results(&[ok, failed]);
+        assert!(summary.starts_with("1/2 sub-tasks succeeded"));
+        assert!(summary.contains("✓ 1. Crate a — Updated header (waited on /ws/NOTICE)"));
+        assert!(summary.contains("✗ 2. Crate b — exceeded its budget of 2 tool calls"));
+    }
+}
@@ -4,7 +4,7 @@
 //! are summarized into a compact representation to preserve important context
 //! while reducing token usage.
 
-use crate::brain::{Brain, LlmProvider};
+use crate::brain::{Brain, LlmProvider, TokenCounter};
 use crate::types::{CompletionRequest, Content, Message, Role};
 use std::sync::Arc;
 
@@ -63,9 +63,10 @@ impl ContextSummarizer {
             _ => String::from("[Summary unavailable]"),
         };
 
-        // Estimate tokens saved (rough: original messages minus summary)
-        let original_tokens: usize = messages.iter().map(estimate_message_tokens).sum();
-        let summary_tokens = summary_text.len() / 4; // rough estimate
+        // Tokens saved: original messages minus summary, in the model's tokenizer
+        let counter = TokenCounter::for_model(self.provider.model_name());
+        let original_tokens: usize = messages.iter().map(|m| counter.count_message(m)).sum();
+        let summary_tokens = counter.count(&summary_text);
 
         Ok(ContextSummary {
             text: summary_text,
@@ -74,6 +75,37 @@ impl ContextSummarizer {
         })
     }
 
+    /// Run a prompt on the summarization route and return the reply text.
+    /// `model` overrides the provider's model for this request.
+    pub async fn complete(
+        &self,
+        prompt: String,
+        model: Option<String>,
+    ) -> Result<String, SummarizeError> {
+        let request = CompletionRequest {
+            messages: vec![Message::user(prompt)],
+            tools: None,
+            temperature: 0.0,
+            max_tokens: Some(800),
+            stop_sequences: Vec::new(),
+            model,
+        };
+        let response = self
+            .provider
+            .complete(request)
+            .await
+            .map_err(|e| SummarizeError::LlmError(e.to_string()))?;
+        match response.message.content {
+            Content::Text { text } => Ok(text),
+            _ => Err(SummarizeError::LlmError("reply was not text".into())),
+        }
+    }
+
+    /// Model the summarization route uses by default.
+    pub fn model_name(&self) -> &str {
+        self.provider.model_name()
+    }
+
     /// Check if summarization is needed based on context usage.
     pub fn should_summarize(context_ratio: f32, threshold: f32) -> bool {
         context_ratio >= threshold
@@ -91,6 +123,14 @@ fn build_summarization_prompt(messages: &[Message]) -> String {
          Conversation:\n",
     );
 
+    prompt.push_str(&transcript(messages));
+    prompt.push_str("\nProvide a concise summary (3-5 sentences) capturing the essential context:");
+    prompt
+}
+
+/// Render messages as `Role: text` lines for a prompt.
+pub(crate) fn transcript(messages: &[Message]) -> String {
+    let mut transcript = String::new();
     for msg in messages {
         let role = match msg.role {
             Role::User => "User",
@@ -117,29 +157,11 @@ fn build_summarization_prompt(messages: &[Message]) -> String {
                 })
                 .collect::<Vec<_>>()
                 .join(" "),
+            Content::Attachment { handle, .. } => handle.reference(),
         };
-        prompt.push_str(&format!("{}: {}\n", role, text));
+        transcript.push_str(&format!("{}: {}\n", role, text));
     }
-
-    prompt.push_str("\nProvide a concise summary (3-5 sentences) capturing the essential context:");
-    prompt
-}
-
-/// Rough token estimation for a message.
-fn estimate_message_tokens(msg: &Message) -> usize {
-    let text_len = match &msg.content {
-        Content::Text { text } => text.len(),
-        Content::ToolCall { arguments, .. } => arguments.to_string().len(),
-        Content::ToolResult { output, .. } => output.len(),
-        Content::MultiPart { parts } => parts
-            .iter()
-            .map(|p| match p {
-                Content::Text { text } => text.len(),
-                _ => 0,
-            })
-            .sum(),
-    };
-    text_len / 4 + 4 // rough: 4 chars per token + overhead
+    transcript
 }
 
 /// Errors during summarization.
@@ -345,11 +367,20 @@ mod tests {
         assert!(prompt.contains("Summarize"));
     }
 
-    #[test]
-    fn test_estimate_message_tokens() {
-        let msg = Message::user("Hello world, this is a test message");
-        let tokens = estimate_message_tokens(&msg);
-        assert!(tokens > 0);
+    #[tokio::test]
+    async fn test_summarize_counts_tokens_saved() {
+        let provider = Arc::new(MockLlmProvider::new());
+        let summarizer = ContextSummarizer::new(provider);
+        let long = "The deployment failed because the migration timed out. ".repeat(40);
+        let messages = vec![Message::user(&long), Message::assistant(&long)];
+        let result = summarizer.summarize(&messages).await.unwrap();
+        let counter = TokenCounter::for_model(MockLlmProvider::new().model_name());
+        let original = counter.count_message(&messages[0]) * 2;
+        assert_eq!(
+            result.tokens_saved,
+            original.saturating_sub(counter.count(&result.text))
+        );
+        assert!(result.tokens_saved > 0);
     }
 
     #[tokio::test]
@@ -0,0 +1,575 @@
+//! Resumable plan tasks.
+//!
+//! While a plan executes, the agent checkpoints its progress after every
+//! step to `.rustant/tasks/<task id>.json`: the plan with each step's status
+//! and outcome, the files each step changed (their content before the step
+//! and a SHA-256 after it), and facts learned along the way. When a task
+//! dies partway — a provider outage, Ctrl-C, a tool crash — `/continue`
+//! loads the checkpoint, warns about files changed outside the task since,
+//! settles any partially applied step (kept when its `verify` command
+//! passes, otherwise rolled back) and resumes from the first incomplete step
+//! instead of replanning.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::plan::{ExecutionPlan, PlanStatus, StepStatus};
+
+/// Directory, relative to the workspace, holding task checkpoints.
+const TASKS_DIR: &str = ".rustant/tasks";
+
+/// Characters of a step's outcome kept in the resume summary.
+const SUMMARY_RESULT_CHARS: usize = 200;
+
+/// Files one plan step changed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StepRecord {
+    pub index: usize,
+    /// Content of each changed file before the step, `None` when the file
+    /// did not exist. Files that are not UTF-8 text are left out and cannot
+    /// be rolled back.
+    #[serde(default)]
+    pub before: BTreeMap<PathBuf, Option<String>>,
+    /// SHA-256 of each changed file once the step ended, `None` when the
+    /// step deleted it.
+    #[serde(default)]
+    pub after: BTreeMap<PathBuf, Option<String>>,
+    /// Whether the step ended; a step that never did was interrupted.
+    #[serde(default)]
+    pub finished: bool,
+}
+
+/// A file whose content no longer matches what the task left behind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDrift {
+    pub path: PathBuf,
+    /// Step that last wrote the file.
+    pub step: usize,
+    /// Whether the file was deleted since (or recreated after a delete).
+    pub existence_changed: bool,
+}
+
+/// How a partially applied step was settled before resuming.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartialStep {
+    /// Its `verify` command passed, so the step counts as completed.
+    Verified { index: usize },
+    /// Its file changes were undone and the step will run again.
+    RolledBack { index: usize, files: Vec<PathBuf> },
+    /// Some changes could not be undone; the step still runs again.
+    RollbackIncomplete { index: usize, error: String },
+}
+
+/// What `/continue` found and did before resuming.
+#[derive(Debug, Clone)]
+pub struct ResumeReport {
+    /// The task being continued.
+    pub task_id: Uuid,
+    pub goal: String,
+    pub completed_steps: usize,
+    pub total_steps: usize,
+    /// First step that will run, if any remain.
+    pub resume_from: Option<usize>,
+    pub drift: Vec<FileDrift>,
+    pub partial: Option<PartialStep>,
+}
+
+impl ResumeReport {
+    /// Multi-line notice shown before the task resumes.
+    pub fn render(&self) -> String {
+        let mut text = format!(
+            "Continuing \"{}\": {}/{} steps done",
+            self.goal, self.completed_steps, self.total_steps
+        );
+        match self.resume_from {
+            Some(index) => text.push_str(&format!(", resuming at step {}", index + 1)),
+            None => text.push_str(", nothing left to run"),
+        }
+        for drift in &self.drift {
+            let change = if drift.existence_changed {
+                "was deleted or recreated"
+            } else {
+                "changed"
+            };
+            text.push_str(&format!(
+                "\n  ! {} {} outside the task since step {}",
+                drift.path.display(),
+                change,
+                drift.step + 1
+            ));
+        }
+        match &self.partial {
+            Some(PartialStep::Verified { index }) => text.push_str(&format!(
+                "\n  Step {} had finished before the interruption (verify passed)",
+                index + 1
+            )),
+            Some(PartialStep::RolledBack { index, files }) => text.push_str(&format!(
+                "\n  Step {} was interrupted; rolled back {} file(s) and will run again",
+                index + 1,
+                files.len()
+            )),
+            Some(PartialStep::RollbackIncomplete { index, error }) => text.push_str(&format!(
+                "\n  ! Step {} was interrupted and could not be fully rolled back: {}",
+                index + 1,
+                error
+            )),
+            None => {}
+        }
+        text
+    }
+}
+
+/// One run of a plan task, kept in the session metadata. A continuation
+/// links to the run it picked up from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskRun {
+    pub task_id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuation_of: Option<Uuid>,
+    pub goal: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub steps_completed: usize,
+    pub steps_total: usize,
+    pub cost_usd: f64,
+    pub success: bool,
+}
+
+impl TaskRun {
+    pub fn duration(&self) -> chrono::Duration {
+        self.finished_at - self.started_at
+    }
+}
+
+/// Checkpointed progress of a plan task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskCheckpoint {
+    pub task_id: Uuid,
+    /// The run this one continues, when it was resumed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuation_of: Option<Uuid>,
+    pub plan: ExecutionPlan,
+    #[serde(default)]
+    pub steps: Vec<StepRecord>,
+    /// Facts learned while the task ran.
+    #[serde(default)]
+    pub facts: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Cost of this run so far.
+    #[serde(default)]
+    pub cost_usd: f64,
+}
+
+impl TaskCheckpoint {
+    pub fn new(task_id: Uuid, plan: ExecutionPlan) -> Self {
+        let now = Utc::now();
+        Self {
+            task_id,
+            continuation_of: None,
+            plan,
+            steps: Vec::new(),
+            facts: Vec::new(),
+            started_at: now,
+            updated_at: now,
+            cost_usd: 0.0,
+        }
+    }
+
+    /// A checkpoint for a new run picking up where this one stopped. Step
+    /// records carry over so later resumes still detect drift.
+    pub fn continuation(&self, task_id: Uuid) -> Self {
+        Self {
+            task_id,
+            continuation_of: Some(self.task_id),
+            started_at: Utc::now(),
+            cost_usd: 0.0,
+            ..self.clone()
+        }
+    }
+
+    fn dir(workspace: &Path) -> PathBuf {
+        workspace.join(TASKS_DIR)
+    }
+
+    fn path(&self, workspace: &Path) -> PathBuf {
+        Self::dir(workspace).join(format!("{}.json", self.task_id))
+    }
+
+    pub fn save(&mut self, workspace: &Path) -> std::io::Result<()> {
+        self.updated_at = Utc::now();
+        let path = self.path(workspace);
+        std::fs::create_dir_all(Self::dir(workspace))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    pub fn remove(&self, workspace: &Path) -> std::io::Result<()> {
+        match std::fs::remove_file(self.path(workspace)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// The most recently updated checkpoint of a task that did not finish.
+    pub fn load_latest(workspace: &Path) -> Option<Self> {
+        std::fs::read_dir(Self::dir(workspace))
+            .ok()?
+            .flatten()
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+            .filter_map(|data| serde_json::from_str::<Self>(&data).ok())
+            .filter(|c| !c.is_finished())
+            .max_by_key(|c| c.updated_at)
+    }
+
+    /// Whether the plan ran to completion or was cancelled.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.plan.status,
+            PlanStatus::Completed | PlanStatus::Cancelled
+        )
+    }
+
+    pub fn completed_steps(&self) -> usize {
+        self.plan
+            .steps
+            .iter()
+            .filter(|s| s.status == StepStatus::Completed)
+            .count()
+    }
+
+    /// The first step that is neither completed nor skipped.
+    pub fn first_incomplete(&self) -> Option<usize> {
+        self.plan
+            .steps
+            .iter()
+            .find(|s| !matches!(s.status, StepStatus::Completed | StepStatus::Skipped))
+            .map(|s| s.index)
+    }
+
+    fn record(&self, index: usize) -> Option<&StepRecord> {
+        self.steps.iter().find(|r| r.index == index)
+    }
+
+    /// Note that `index` started, forgetting changes from an earlier attempt.
+    pub fn start_step(&mut self, index: usize) {
+        self.steps.retain(|r| r.index != index);
+        self.steps.push(StepRecord {
+            index,
+            ..Default::default()
+        });
+    }
+
+    /// Remember `path`'s content before the running step first changes it.
+    pub fn record_before(&mut self, path: &Path) {
+        let Some(record) = self.steps.last_mut().filter(|r| !r.finished) else {
+            return;
+        };
+        if record.before.contains_key(path) {
+            return;
+        }
+        let content = match std::fs::read(path) {
+            Ok(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => Some(text),
+                Err(_) => return,
+            },
+            Err(_) => None,
+        };
+        record.before.insert(path.to_path_buf(), content);
+    }
+
+    /// Note that the running step ended, hashing the files it changed.
+    pub fn finish_step(&mut self) {
+        if let Some(record) = self.steps.last_mut() {
+            record.after = record
+                .before
+                .keys()
+                .map(|path| (path.clone(), file_sha256(path)))
+                .collect();
+            record.finished = true;
+        }
+    }
+
+    /// Files changed since the completed steps that last wrote them.
+    pub fn workspace_drift(&self) -> Vec<FileDrift> {
+        let mut expected: BTreeMap<&Path, (usize, &Option<String>)> = BTreeMap::new();
+        for record in &self.steps {
+            let completed = self
+                .plan
+                .steps
+                .get(record.index)
+                .is_some_and(|s| s.status == StepStatus::Completed);
+            if completed && record.finished {
+                for (path, hash) in &record.after {
+                    expected.insert(path, (record.index, hash));
+                }
+            }
+        }
+        expected
+            .into_iter()
+            .filter_map(|(path, (step, hash))| {
+                let actual = file_sha256(path);
+                (actual != *hash).then(|| FileDrift {
+                    path: path.to_path_buf(),
+                    step,
+                    existence_changed: actual.is_some() != hash.is_some(),
+                })
+            })
+            .collect()
+    }
+
+    /// The first incomplete step, when it changed files before it stopped.
+    pub fn partial_step(&self) -> Option<usize> {
+        let index = self.first_incomplete()?;
+        self.record(index)
+            .filter(|r| !r.before.is_empty())
+            .map(|r| r.index)
+    }
+
+    /// Restore the files step `index` changed to their content before it.
+    pub fn rollback(&mut self, index: usize) -> std::io::Result<Vec<PathBuf>> {
+        let Some(record) = self.record(index) else {
+            return Ok(Vec::new());
+        };
+        let mut restored = Vec::new();
+        for (path, content) in &record.before {
+            match content {
+                Some(content) => {
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(path, content)?;
+                }
+                None if path.exists() => std::fs::remove_file(path)?,
+                None => {}
+            }
+            restored.push(path.clone());
+        }
+        self.steps.retain(|r| r.index != index);
+        Ok(restored)
+    }
+
+    /// Prepare the plan to run again from its first incomplete step.
+    pub fn reset_incomplete(&mut self) {
+        // Sub-tasks run before the first step, so they are done once any step started.
+        if self
+            .plan
+            .steps
+            .iter()
+            .any(|s| s.status != StepStatus::Pending)
+        {
+            self.plan.subtasks.clear();
+        }
+        for step in &mut self.plan.steps {
+            if matches!(step.status, StepStatus::InProgress | StepStatus::Failed) {
+                step.status = StepStatus::Pending;
+                step.result = None;
+            }
+        }
+        self.plan.status = PlanStatus::Executing;
+        self.plan.current_step = None;
+    }
+
+    /// Compact summary of the work already done, for the model's context.
+    pub fn summary(&self) -> String {
+        let mut text = format!(
+            "You are continuing an interrupted task: {}\n\
+             Completed steps (do not redo them):\n",
+            self.plan.goal
+        );
+        for step in self
+            .plan
+            .steps
+            .iter()
+            .filter(|s| s.status == StepStatus::Completed)
+        {
+            let result = step.result.as_deref().unwrap_or("done");
+            text.push_str(&format!(
+                "{}. {} — {}\n",
+                step.index + 1,
+                step.description,
+                truncate(result.trim())
+            ));
+        }
+        let files: Vec<String> = self
+            .steps
+            .iter()
+            .filter(|r| r.finished)
+            .flat_map(|r| r.after.keys())
+            .map(|p| p.display().to_string())
+            .collect();
+        if !files.is_empty() {
+            text.push_str(&format!("Files already modified: {}\n", files.join(", ")));
+        }
+        if !self.facts.is_empty() {
+            text.push_str("Facts gathered:\n");
+            for fact in &self.facts {
+                text.push_str(&format!("- {}\n", fact));
+            }
+        }
+        text
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(SUMMARY_RESULT_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+fn file_sha256(path: &Path) -> Option<String> {
+    use crate::pairing::hex;
+    use sha2::{Digest, Sha256};
+
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(hex::encode(hasher.finalize()))
+}
+
+/// Whether a `shell_exec` result reports a zero exit code.
+pub fn verification_passed(output: &str) -> bool {
+    output.starts_with("Exit code: 0\n") || output.trim() == "Exit code: 0"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::PlanStep;
+
+    fn plan(steps: usize) -> ExecutionPlan {
+        let mut plan = ExecutionPlan::new("Refactor auth", "three steps");
+        plan.steps = (0..steps)
+            .map(|index| PlanStep {
+                index,
+                description: format!("Step {}", index + 1),
+                ..Default::default()
+            })
+            .collect();
+        plan.status = PlanStatus::Executing;
+        plan
+    }
+
+    #[test]
+    fn test_checkpoint_roundtrip_and_latest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut done = TaskCheckpoint::new(Uuid::new_v4(), plan(1));
+        done.plan.status = PlanStatus::Completed;
+        done.save(dir.path()).unwrap();
+        assert!(TaskCheckpoint::load_latest(dir.path()).is_none());
+
+        let mut checkpoint = TaskCheckpoint::new(Uuid::new_v4(), plan(3));
+        checkpoint.plan.complete_step(0, "read config");
+        checkpoint.facts.push("auth lives in src/auth".into());
+        checkpoint.save(dir.path()).unwrap();
+
+        let loaded = TaskCheckpoint::load_latest(dir.path()).unwrap();
+        assert_eq!(loaded.task_id, checkpoint.task_id);
+        assert_eq!(loaded.completed_steps(), 1);
+        assert_eq!(loaded.first_incomplete(), Some(1));
+        let summary = loaded.summary();
+        assert!(summary.contains("1. Step 1 — read config"));
+        assert!(summary.contains("auth lives in src/auth"));
+
+        loaded.remove(dir.path()).unwrap();
+        assert!(TaskCheckpoint::load_latest(dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_drift_after_completed_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "old").unwrap();
+
+        let mut checkpoint = TaskCheckpoint::new(Uuid::new_v4(), plan(2));
+        checkpoint.start_step(0);
+        checkpoint.record_before(&file);
+        std::fs::write(&file, "new").unwrap();
+        checkpoint.finish_step();
+        checkpoint.plan.complete_step(0, "edited");
+        assert!(checkpoint.workspace_drift().is_empty());
+
+        std::fs::write(&file, "edited by hand").unwrap();
+        let drift = checkpoint.workspace_drift();
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].path, file);
+        assert!(!drift[0].existence_changed);
+
+        std::fs::remove_file(&file).unwrap();
+        assert!(checkpoint.workspace_drift()[0].existence_changed);
+    }
+
+    #[test]
+    fn test_partial_step_rolls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let edited = dir.path().join("lib.rs");
+        let created = dir.path().join("new.rs");
+        std::fs::write(&edited, "original").unwrap();
+
+        let mut checkpoint = TaskCheckpoint::new(Uuid::new_v4(), plan(2));
+        checkpoint.plan.complete_step(0, "done");
+        checkpoint.start_step(1);
+        checkpoint.plan.steps[1].status = StepStatus::InProgress;
+        checkpoint.record_before(&edited);
+        checkpoint.record_before(&created);
+        std::fs::write(&edited, "half-written").unwrap();
+        std::fs::write(&created, "partial").unwrap();
+
+        assert_eq!(checkpoint.partial_step(), Some(1));
+        let restored = checkpoint.rollback(1).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(std::fs::read_to_string(&edited).unwrap(), "original");
+        assert!(!created.exists());
+        assert_eq!(checkpoint.partial_step(), None);
+
+        checkpoint.reset_incomplete();
+        assert_eq!(checkpoint.plan.next_pending_step(), Some(1));
+    }
+
+    #[test]
+    fn test_continuation_links_to_original() {
+        let checkpoint = TaskCheckpoint::new(Uuid::new_v4(), plan(1));
+        let next = checkpoint.continuation(Uuid::new_v4());
+        assert_eq!(next.continuation_of, Some(checkpoint.task_id));
+        assert_ne!(next.task_id, checkpoint.task_id);
+    }
+
+    #[test]
+    fn test_resume_report_render() {
+        let report = ResumeReport {
+            task_id: Uuid::new_v4(),
+            goal: "Refactor auth".into(),
+            completed_steps: 6,
+            total_steps: 9,
+            resume_from: Some(6),
+            drift: vec![FileDrift {
+                path: PathBuf::from("src/auth.rs"),
+                step: 2,
+                existence_changed: false,
+            }],
+            partial: Some(PartialStep::RolledBack {
+                index: 6,
+                files: vec![PathBuf::from("src/auth/mod.rs")],
+            }),
+        };
+        let text = report.render();
+        assert!(
+            text.starts_with("Continuing \"Refactor auth\": 6/9 steps done, resuming at step 7")
+        );
+        assert!(text.contains("src/auth.rs changed outside the task since step 3"));
+        assert!(text.contains("Step 7 was interrupted; rolled back 1 file(s)"));
+    }
+
+    #[test]
+    fn test_verification_output() {
+        assert!(verification_passed("Exit code: 0\n\n--- stdout ---\nok"));
+        assert!(!verification_passed("Exit code: 1\n\n--- stdout ---\n"));
+        assert!(!verification_passed("Exit code: 10\n"));
+    }
+}
@@ -0,0 +1,400 @@
+//! OpenTelemetry export for traces and metrics.
+//!
+//! `[telemetry]` in config.toml points Rustant at an OTLP/HTTP collector.
+//! Spans emitted through `tracing` (tasks, tool calls, LLM requests) are
+//! exported with their fields as attributes, and the instruments behind
+//! [`crate::metrics`] are exported on a fixed interval.
+//!
+//! The exporter is only compiled with the `otel` feature. Export runs on
+//! background threads with a bounded queue: when the collector is slow or
+//! down, spans are dropped rather than blocking the agent.
+
+use crate::credentials::CredentialStore;
+use crate::secret_ref::{SecretRef, SecretResolveError, SecretResolver};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use tracing::Subscriber;
+use tracing_subscriber::Layer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Wire encoding for OTLP over HTTP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OtlpProtocol {
+    #[default]
+    #[serde(rename = "http/protobuf")]
+    HttpProtobuf,
+    #[serde(rename = "http/json")]
+    HttpJson,
+}
+
+/// Configuration for OpenTelemetry export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Whether to export telemetry.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Base URL of the OTLP/HTTP collector; `/v1/traces` and `/v1/metrics`
+    /// are appended.
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+    #[serde(default)]
+    pub protocol: OtlpProtocol,
+    /// Extra request headers, e.g. `authorization = "env:OTEL_TOKEN"`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, SecretRef>,
+    /// Value of the `service.name` resource attribute.
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    /// Fraction of traces to sample, from 0.0 to 1.0.
+    #[serde(default = "default_sampling_ratio")]
+    pub sampling_ratio: f64,
+    /// How often metrics are exported.
+    #[serde(default = "default_metrics_interval_secs")]
+    pub metrics_interval_secs: u64,
+    /// Spans buffered for export; further spans are dropped when full.
+    #[serde(default = "default_max_queue_size")]
+    pub max_queue_size: usize,
+    /// Timeout for a single export request.
+    #[serde(default = "default_export_timeout_secs")]
+    pub export_timeout_secs: u64,
+}
+
+fn default_endpoint() -> String {
+    "http://localhost:4318".into()
+}
+
+fn default_service_name() -> String {
+    "rustant".into()
+}
+
+fn default_sampling_ratio() -> f64 {
+    1.0
+}
+
+fn default_metrics_interval_secs() -> u64 {
+    60
+}
+
+fn default_max_queue_size() -> usize {
+    2048
+}
+
+fn default_export_timeout_secs() -> u64 {
+    5
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_endpoint(),
+            protocol: OtlpProtocol::default(),
+            headers: BTreeMap::new(),
+            service_name: default_service_name(),
+            sampling_ratio: default_sampling_ratio(),
+            metrics_interval_secs: default_metrics_interval_secs(),
+            max_queue_size: default_max_queue_size(),
+            export_timeout_secs: default_export_timeout_secs(),
+        }
+    }
+}
+
+/// Errors from setting up telemetry export.
+#[derive(Debug, thiserror::Error)]
+pub enum TelemetryError {
+    #[error("telemetry endpoint is empty")]
+    MissingEndpoint,
+
+    #[error("sampling_ratio must be between 0.0 and 1.0, got {0}")]
+    InvalidSamplingRatio(f64),
+
+    #[error("failed to resolve telemetry header '{name}': {source}")]
+    Header {
+        name: String,
+        source: SecretResolveError,
+    },
+
+    #[error("failed to build OTLP exporter: {0}")]
+    Exporter(String),
+
+    #[error(
+        "telemetry is enabled but this build lacks OpenTelemetry support (rebuild with --features otel)"
+    )]
+    NotCompiled,
+}
+
+impl TelemetryConfig {
+    /// Check the settings that would otherwise fail at export time.
+    pub fn validate(&self) -> Result<(), TelemetryError> {
+        if self.endpoint.trim().is_empty() {
+            return Err(TelemetryError::MissingEndpoint);
+        }
+        if !(0.0..=1.0).contains(&self.sampling_ratio) {
+            return Err(TelemetryError::InvalidSamplingRatio(self.sampling_ratio));
+        }
+        Ok(())
+    }
+
+    /// Resolve header secrets to their values.
+    pub fn resolve_headers(
+        &self,
+        store: &dyn CredentialStore,
+    ) -> Result<HashMap<String, String>, TelemetryError> {
+        self.headers
+            .iter()
+            .map(|(name, secret)| {
+                SecretResolver::resolve(secret, store)
+                    .map(|value| (name.clone(), value))
+                    .map_err(|source| TelemetryError::Header {
+                        name: name.clone(),
+                        source,
+                    })
+            })
+            .collect()
+    }
+
+    /// Full URL for one OTLP signal, e.g. `signal_endpoint("traces")`.
+    pub fn signal_endpoint(&self, signal: &str) -> String {
+        format!("{}/v1/{}", self.endpoint.trim_end_matches('/'), signal)
+    }
+}
+
+/// Running exporters. Pending data is flushed when this is dropped.
+pub struct Telemetry {
+    #[cfg(feature = "otel")]
+    tracer_provider: opentelemetry_sdk::trace::SdkTracerProvider,
+    #[cfg(feature = "otel")]
+    meter_provider: opentelemetry_sdk::metrics::SdkMeterProvider,
+}
+
+impl Telemetry {
+    /// Start exporting according to `config`. Returns `Ok(None)` when
+    /// telemetry is disabled.
+    pub fn init(
+        config: &TelemetryConfig,
+        store: &dyn CredentialStore,
+    ) -> Result<Option<Self>, TelemetryError> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        config.validate()?;
+        let headers = config.resolve_headers(store)?;
+        Self::start(config, headers).map(Some)
+    }
+
+    #[cfg(not(feature = "otel"))]
+    fn start(
+        _config: &TelemetryConfig,
+        _headers: HashMap<String, String>,
+    ) -> Result<Self, TelemetryError> {
+        Err(TelemetryError::NotCompiled)
+    }
+
+    #[cfg(feature = "otel")]
+    fn start(
+        config: &TelemetryConfig,
+        headers: HashMap<String, String>,
+    ) -> Result<Self, TelemetryError> {
+        use opentelemetry::metrics::MeterProvider as _;
+        use opentelemetry_otlp::{
+            MetricExporter, Protocol, SpanExporter, WithExportConfig, WithHttpConfig,
+        };
+        use opentelemetry_sdk::Resource;
+        use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+        use opentelemetry_sdk::trace::{
+            BatchConfigBuilder, BatchSpanProcessor, Sampler, SdkTracerProvider,
+        };
+        use std::time::Duration;
+
+        let protocol = match config.protocol {
+            OtlpProtocol::HttpProtobuf => Protocol::HttpBinary,
+            OtlpProtocol::HttpJson => Protocol::HttpJson,
+        };
+        let timeout = Duration::from_secs(config.export_timeout_secs);
+        let resource = Resource::builder()
+            .with_service_name(config.service_name.clone())
+            .build();
+
+        let span_exporter = SpanExporter::builder()
+            .with_http()
+            .with_protocol(protocol)
+            .with_endpoint(config.signal_endpoint("traces"))
+            .with_headers(headers.clone())
+            .with_timeout(timeout)
+            .build()
+            .map_err(|e| TelemetryError::Exporter(e.to_string()))?;
+        let processor = BatchSpanProcessor::builder(span_exporter)
+            .with_batch_config(
+                BatchConfigBuilder::default()
+                    .with_max_queue_size(config.max_queue_size.max(1))
+                    .with_max_export_batch_size(config.max_queue_size.clamp(1, 512))
+                    .build(),
+            )
+            .build();
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_span_processor(processor)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                config.sampling_ratio,
+            ))))
+            .with_resource(resource.clone())
+            .build();
+
+        let metric_exporter = MetricExporter::builder()
+            .with_http()
+            .with_protocol(protocol)
+            .with_endpoint(config.signal_endpoint("metrics"))
+            .with_headers(headers)
+            .with_timeout(timeout)
+            .build()
+            .map_err(|e| TelemetryError::Exporter(e.to_string()))?;
+        let reader = PeriodicReader::builder(metric_exporter)
+            .with_interval(Duration::from_secs(config.metrics_interval_secs.max(1)))
+            .build();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(reader)
+            .with_resource(resource)
+            .build();
+        crate::metrics::install_instruments(&meter_provider.meter("rustant"));
+
+        Ok(Self {
+            tracer_provider,
+            meter_provider,
+        })
+    }
+
+    /// A `tracing` layer that exports spans through this instance.
+    pub fn tracing_layer<S>(&self) -> Box<dyn Layer<S> + Send + Sync>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+    {
+        #[cfg(feature = "otel")]
+        {
+            use opentelemetry::trace::TracerProvider as _;
+            use tracing_subscriber::filter::LevelFilter;
+            Box::new(
+                tracing_opentelemetry::layer()
+                    .with_tracer(self.tracer_provider.tracer("rustant"))
+                    .with_filter(LevelFilter::INFO),
+            )
+        }
+        #[cfg(not(feature = "otel"))]
+        {
+            Box::new(tracing_subscriber::layer::Identity::new())
+        }
+    }
+
+    /// Export everything buffered so far. Blocks until the exporters finish
+    /// or time out.
+    pub fn force_flush(&self) {
+        #[cfg(feature = "otel")]
+        {
+            let _ = self.tracer_provider.force_flush();
+            let _ = self.meter_provider.force_flush();
+        }
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        {
+            let _ = self.tracer_provider.shutdown();
+            let _ = self.meter_provider.shutdown();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::InMemoryCredentialStore;
+
+    #[test]
+    fn test_config_defaults() {
+        let config: TelemetryConfig = toml::from_str("enabled = true").unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.endpoint, "http://localhost:4318");
+        assert_eq!(config.protocol, OtlpProtocol::HttpProtobuf);
+        assert_eq!(config.service_name, "rustant");
+        assert_eq!(config.sampling_ratio, 1.0);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_parses_protocol_and_headers() {
+        let config: TelemetryConfig = toml::from_str(
+            r#"
+            endpoint = "https://otel.example.com/"
+            protocol = "http/json"
+            headers = { authorization = "env:RUSTANT_TEST_OTEL_AUTH" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.protocol, OtlpProtocol::HttpJson);
+        assert!(config.headers["authorization"].is_env());
+        assert_eq!(
+            config.signal_endpoint("traces"),
+            "https://otel.example.com/v1/traces"
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_values() {
+        let config = TelemetryConfig {
+            sampling_ratio: 1.5,
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(TelemetryError::InvalidSamplingRatio(_))
+        ));
+        let config = TelemetryConfig {
+            endpoint: " ".into(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(TelemetryError::MissingEndpoint)
+        ));
+    }
+
+    #[test]
+    fn test_resolve_headers_from_keychain() {
+        let store = InMemoryCredentialStore::new();
+        store.store_key("otel", "Bearer abc").unwrap();
+        let mut config = TelemetryConfig::default();
+        config
+            .headers
+            .insert("authorization".into(), SecretRef::keychain("otel"));
+        let headers = config.resolve_headers(&store).unwrap();
+        assert_eq!(headers["authorization"], "Bearer abc");
+
+        config
+            .headers
+            .insert("x-missing".into(), SecretRef::keychain("nope"));
+        let err = config.resolve_headers(&store).unwrap_err();
+        assert!(err.to_string().contains("x-missing"));
+    }
+
+    #[test]
+    fn test_disabled_config_starts_nothing() {
+        let store = InMemoryCredentialStore::new();
+        let telemetry = Telemetry::init(&TelemetryConfig::default(), &store).unwrap();
+        assert!(telemetry.is_none());
+    }
+
+    #[cfg(not(feature = "otel"))]
+    #[test]
+    fn test_enabled_without_feature_reports_not_compiled() {
+        let store = InMemoryCredentialStore::new();
+        let config = TelemetryConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            Telemetry::init(&config, &store),
+            Err(TelemetryError::NotCompiled)
+        ));
+    }
+}
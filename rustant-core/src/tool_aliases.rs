@@ -0,0 +1,281 @@
+//! Tool aliases — old tool names that keep working after a rename.
+//!
+//! When a tool is renamed or folded into another, its old name stays as an
+//! alias of the canonical tool, so saved sessions, skills that require it,
+//! workflows and external MCP clients keep working. Each alias follows a
+//! deprecation schedule checked against the running Rustant version:
+//!
+//! 1. **Deprecated** — calls resolve to the canonical tool, log a warning and
+//!    are counted in the `rustant.tool.alias_calls` metric. The alias is
+//!    listed next to its tool when `[tools] list_aliases` is on.
+//! 2. **Hidden** — calls still resolve, but the alias is never listed.
+//! 3. **Removed** — calls fail with [`ToolError::Removed`], which names the
+//!    replacement.
+//!
+//! Skills and workflows resolve aliases when they are loaded, so deprecated
+//! references are reported before anything runs.
+
+use crate::error::ToolError;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use tracing::warn;
+
+/// The running Rustant version, which alias schedules are checked against.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Where an alias is in its deprecation schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AliasStage {
+    Deprecated,
+    Hidden,
+    Removed,
+}
+
+/// An old name of a tool and its deprecation schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolAlias {
+    pub alias: String,
+    /// Name of the tool the alias resolves to.
+    pub canonical: String,
+    /// Version in which the alias was deprecated.
+    pub deprecated_in: String,
+    /// Version from which the alias is no longer listed.
+    pub hidden_in: Option<String>,
+    /// Version from which calls through the alias fail.
+    pub removed_in: Option<String>,
+}
+
+impl ToolAlias {
+    pub fn new(
+        alias: impl Into<String>,
+        canonical: impl Into<String>,
+        deprecated_in: impl Into<String>,
+    ) -> Self {
+        Self {
+            alias: alias.into(),
+            canonical: canonical.into(),
+            deprecated_in: deprecated_in.into(),
+            hidden_in: None,
+            removed_in: None,
+        }
+    }
+
+    pub fn hidden_in(mut self, version: impl Into<String>) -> Self {
+        self.hidden_in = Some(version.into());
+        self
+    }
+
+    pub fn removed_in(mut self, version: impl Into<String>) -> Self {
+        self.removed_in = Some(version.into());
+        self
+    }
+
+    /// Stage of the schedule at `version`.
+    pub fn stage(&self, version: &str) -> AliasStage {
+        let reached =
+            |v: &Option<String>| v.as_deref().is_some_and(|v| version_at_least(version, v));
+        if reached(&self.removed_in) {
+            AliasStage::Removed
+        } else if reached(&self.hidden_in) {
+            AliasStage::Hidden
+        } else {
+            AliasStage::Deprecated
+        }
+    }
+
+    /// One-line notice telling callers what to use instead.
+    pub fn notice(&self) -> String {
+        let mut notice = format!(
+            "tool '{}' is deprecated since {}; use '{}'",
+            self.alias, self.deprecated_in, self.canonical
+        );
+        if let Some(removed) = &self.removed_in {
+            notice.push_str(&format!(" (removed in {})", removed));
+        }
+        notice
+    }
+
+    /// The error returned for a call through the alias once it is removed.
+    pub fn removed_error(&self) -> ToolError {
+        ToolError::Removed {
+            name: self.alias.clone(),
+            replacement: self.canonical.clone(),
+            version: self.removed_in.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Renamed built-in tools.
+static BUILTIN_ALIASES: LazyLock<Vec<ToolAlias>> = LazyLock::new(|| {
+    vec![
+        ToolAlias::new("macos_app_control", "app_control", "1.0.1")
+            .hidden_in("1.2.0")
+            .removed_in("2.0.0"),
+    ]
+});
+
+/// Aliases of the built-in tool `canonical`.
+pub fn builtin_aliases(canonical: &str) -> Vec<ToolAlias> {
+    BUILTIN_ALIASES
+        .iter()
+        .filter(|a| a.canonical == canonical)
+        .cloned()
+        .collect()
+}
+
+/// Aliases by old name, checked against one version.
+#[derive(Debug, Clone)]
+pub struct AliasTable {
+    aliases: HashMap<String, ToolAlias>,
+    version: String,
+}
+
+impl AliasTable {
+    /// An empty table checked against `version`.
+    pub fn new(version: impl Into<String>) -> Self {
+        Self {
+            aliases: HashMap::new(),
+            version: version.into(),
+        }
+    }
+
+    /// The built-in renames, checked against the running version.
+    pub fn builtin() -> Self {
+        let mut table = Self::new(VERSION);
+        for alias in BUILTIN_ALIASES.iter() {
+            table.insert(alias.clone());
+        }
+        table
+    }
+
+    pub fn insert(&mut self, alias: ToolAlias) {
+        self.aliases.insert(alias.alias.clone(), alias);
+    }
+
+    pub fn get(&self, alias: &str) -> Option<&ToolAlias> {
+        self.aliases.get(alias)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ToolAlias> {
+        self.aliases.values()
+    }
+
+    pub fn stage(&self, alias: &ToolAlias) -> AliasStage {
+        alias.stage(&self.version)
+    }
+
+    /// Resolve a tool call's name to its canonical tool. Names that are not
+    /// aliases resolve to themselves. Calls through a deprecated or hidden
+    /// alias are logged and counted; removed aliases are an error.
+    pub fn resolve<'a>(&'a self, name: &'a str) -> Result<&'a str, ToolError> {
+        let Some(alias) = self.aliases.get(name) else {
+            return Ok(name);
+        };
+        if self.stage(alias) == AliasStage::Removed {
+            return Err(alias.removed_error());
+        }
+        warn!(alias = name, tool = %alias.canonical, "{}", alias.notice());
+        crate::metrics::record_tool_alias_call(name, &alias.canonical);
+        Ok(&alias.canonical)
+    }
+
+    /// Resolve a tool name referenced by a skill or workflow being loaded.
+    /// Returns the canonical name and, for an alias, a warning to report.
+    pub fn check_reference(&self, name: &str) -> Result<(String, Option<String>), ToolError> {
+        match self.aliases.get(name) {
+            None => Ok((name.to_string(), None)),
+            Some(alias) if self.stage(alias) == AliasStage::Removed => Err(alias.removed_error()),
+            Some(alias) => Ok((alias.canonical.clone(), Some(alias.notice()))),
+        }
+    }
+}
+
+impl Default for AliasTable {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// Whether dotted version `version` is at or past `target`. Missing
+/// components count as zero and pre-release suffixes are ignored.
+pub fn version_at_least(version: &str, target: &str) -> bool {
+    fn parts(v: &str) -> Vec<u64> {
+        v.trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    }
+    let (a, b) = (parts(version), parts(target));
+    for i in 0..a.len().max(b.len()) {
+        let (x, y) = (
+            a.get(i).copied().unwrap_or(0),
+            b.get(i).copied().unwrap_or(0),
+        );
+        if x != y {
+            return x > y;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(version: &str) -> AliasTable {
+        let mut table = AliasTable::new(version);
+        table.insert(
+            ToolAlias::new("old_tool", "new_tool", "1.0.0")
+                .hidden_in("1.2.0")
+                .removed_in("2.0.0"),
+        );
+        table
+    }
+
+    #[test]
+    fn test_schedule_stages() {
+        let alias = table("1.0.0").get("old_tool").unwrap().clone();
+        assert_eq!(alias.stage("1.0.0"), AliasStage::Deprecated);
+        assert_eq!(alias.stage("1.1.9"), AliasStage::Deprecated);
+        assert_eq!(alias.stage("1.2.0"), AliasStage::Hidden);
+        assert_eq!(alias.stage("2.0.0-beta.1"), AliasStage::Removed);
+        assert_eq!(alias.stage("10.0"), AliasStage::Removed);
+    }
+
+    #[test]
+    fn test_resolve_alias_until_removed() {
+        assert_eq!(table("1.3.0").resolve("old_tool").unwrap(), "new_tool");
+        assert_eq!(table("1.3.0").resolve("other").unwrap(), "other");
+
+        let err = table("2.0.0").resolve("old_tool").unwrap_err();
+        assert!(matches!(
+            &err,
+            ToolError::Removed { name, replacement, .. } if name == "old_tool" && replacement == "new_tool"
+        ));
+        assert!(err.to_string().contains("use 'new_tool' instead"));
+    }
+
+    #[test]
+    fn test_check_reference_reports_deprecation() {
+        let (canonical, warning) = table("1.0.0").check_reference("old_tool").unwrap();
+        assert_eq!(canonical, "new_tool");
+        assert!(warning.unwrap().contains("use 'new_tool'"));
+        assert_eq!(
+            table("1.0.0").check_reference("new_tool").unwrap(),
+            ("new_tool".to_string(), None)
+        );
+        assert!(table("2.1.0").check_reference("old_tool").is_err());
+    }
+
+    #[test]
+    fn test_builtin_renames() {
+        let table = AliasTable::builtin();
+        assert_eq!(table.resolve("macos_app_control").unwrap(), "app_control");
+        assert_eq!(builtin_aliases("app_control").len(), 1);
+    }
+}
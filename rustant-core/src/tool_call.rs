@@ -0,0 +1,219 @@
+//! Per-call view of tool execution: one-line call summaries and
+//! cancellation of a single slow call.
+//!
+//! A summary names a call the way a person would — `file_read
+//! src/agent/mod.rs L200-400`, `shell_exec cargo test -p rustant-core` —
+//! instead of the raw argument JSON. Summaries appear in REPL progress
+//! lines, gateway `tool_started` events and `tool_call` tracing spans, so
+//! [`call_summary`] makes workspace paths relative, passes the text through
+//! [`redact_secrets`] and caps it at [`MAX_SUMMARY_CHARS`].
+
+use crate::sanitize::redact_secrets;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+/// Longest call summary, in characters, including the tool name.
+pub const MAX_SUMMARY_CHARS: usize = 96;
+
+/// Arguments shown by [`summarize_args`], in order of preference.
+const PRIMARY_KEYS: [&str; 9] = [
+    "command", "path", "file", "url", "query", "pattern", "name", "message", "id",
+];
+
+/// Turns a call's arguments into the text after the tool name. Tools with
+/// a better summary than [`summarize_args`] register one with the agent.
+pub type ArgSummarizer = Box<dyn Fn(&Value) -> String + Send + Sync>;
+
+/// Summarize arguments for a tool without a summarizer of its own: the
+/// `action` of action-dispatch tools, the first primary argument, and a
+/// line range when `start_line`/`end_line` are given.
+pub fn summarize_args(args: &Value) -> String {
+    let mut parts = Vec::new();
+    if let Some(action) = str_arg(args, "action") {
+        parts.push(action.to_string());
+    }
+    if let Some(value) = PRIMARY_KEYS.iter().find_map(|key| str_arg(args, key)) {
+        parts.push(value.to_string());
+    }
+    if let Some(range) = line_range(args) {
+        parts.push(range);
+    }
+    parts.join(" ")
+}
+
+/// Format `start_line`/`end_line` arguments as `L200-400`.
+pub fn line_range(args: &Value) -> Option<String> {
+    let start = args.get("start_line").and_then(Value::as_u64);
+    let end = args.get("end_line").and_then(Value::as_u64);
+    match (start, end) {
+        (Some(start), Some(end)) => Some(format!("L{}-{}", start, end)),
+        (Some(start), None) => Some(format!("L{}-", start)),
+        (None, Some(end)) => Some(format!("L1-{}", end)),
+        (None, None) => None,
+    }
+}
+
+/// Build the summary of a call to `tool` from its argument summary: one
+/// line, workspace paths relative, secrets redacted, at most
+/// [`MAX_SUMMARY_CHARS`] characters.
+pub fn call_summary(tool: &str, arg_summary: &str, workspace: Option<&Path>) -> String {
+    let mut args = arg_summary.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some(workspace) = workspace {
+        args = relativize(&args, workspace);
+    }
+    let text = if args.is_empty() {
+        tool.to_string()
+    } else {
+        format!("{} {}", tool, args)
+    };
+    // Redact before truncating, so a cut can't hide a secret from the patterns.
+    let text = redact_secrets(&text);
+    match text.char_indices().nth(MAX_SUMMARY_CHARS - 1) {
+        Some((end, _)) if text.chars().count() > MAX_SUMMARY_CHARS => {
+            format!("{}…", &text[..end])
+        }
+        _ => text,
+    }
+}
+
+/// Strip the workspace prefix from paths in `text`; the workspace itself
+/// becomes `.`.
+fn relativize(text: &str, workspace: &Path) -> String {
+    let root = workspace.to_string_lossy();
+    let root = root.trim_end_matches('/');
+    if root.is_empty() {
+        return text.to_string();
+    }
+    text.replace(&format!("{}/", root), "")
+        .split(' ')
+        .map(|word| if word == root { "." } else { word })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Cancels the tool call that is running past `[tools] slow_call_secs`,
+/// leaving the rest of the task running. Cloned handles share the call.
+#[derive(Debug, Clone, Default)]
+pub struct SlowCallCanceller {
+    slow_call: Arc<Mutex<Option<CancellationToken>>>,
+}
+
+impl SlowCallCanceller {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the slow call, if one is running. Returns false when no call
+    /// is past the threshold, so the caller can cancel the whole task instead.
+    pub fn cancel_slow_call(&self) -> bool {
+        match self.slow_call.lock() {
+            Ok(mut slot) => slot.take().map(|token| token.cancel()).is_some(),
+            Err(_) => false,
+        }
+    }
+
+    /// Whether a call is currently past the threshold.
+    pub fn has_slow_call(&self) -> bool {
+        self.slow_call.lock().is_ok_and(|slot| slot.is_some())
+    }
+
+    /// Make the running call cancellable through `token`.
+    pub(crate) fn arm(&self, token: CancellationToken) {
+        if let Ok(mut slot) = self.slow_call.lock() {
+            *slot = Some(token);
+        }
+    }
+
+    /// The call finished; cancelling no longer applies to it.
+    pub(crate) fn disarm(&self) {
+        if let Ok(mut slot) = self.slow_call.lock() {
+            *slot = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_summarize_args_primary_argument() {
+        let args = json!({"path": "src/agent/mod.rs", "start_line": 200, "end_line": 400});
+        assert_eq!(summarize_args(&args), "src/agent/mod.rs L200-400");
+        let args = json!({"command": "cargo test -p rustant-core", "path": "ignored"});
+        assert_eq!(summarize_args(&args), "cargo test -p rustant-core");
+        let args = json!({"action": "search", "query": "tokio select"});
+        assert_eq!(summarize_args(&args), "search tokio select");
+        assert_eq!(summarize_args(&json!({"limit": 3})), "");
+    }
+
+    #[test]
+    fn test_call_summary_workspace_relative() {
+        let ws = Path::new("/home/dev/project");
+        let summary = call_summary("file_read", "/home/dev/project/src/main.rs L1-20", Some(ws));
+        assert_eq!(summary, "file_read src/main.rs L1-20");
+        let summary = call_summary("file_list", "/home/dev/project", Some(ws));
+        assert_eq!(summary, "file_list .");
+        let summary = call_summary("file_read", "/home/dev/project-old/a.rs", Some(ws));
+        assert_eq!(summary, "file_read /home/dev/project-old/a.rs");
+        assert_eq!(call_summary("git_status", "", Some(ws)), "git_status");
+    }
+
+    #[test]
+    fn test_call_summary_single_line_and_capped() {
+        let summary = call_summary("shell_exec", "echo one\necho two", None);
+        assert_eq!(summary, "shell_exec echo one echo two");
+        let long = "x".repeat(500);
+        let summary = call_summary("shell_exec", &long, None);
+        assert_eq!(summary.chars().count(), MAX_SUMMARY_CHARS);
+        assert!(summary.ends_with('…'));
+    }
+
+    #[test]
+    fn test_call_summary_redacts_tokens_in_command_line() {
+        let token = format!("ghp_{}", "a".repeat(36));
+        let command = format!(
+            "curl -H 'Authorization: Bearer {}' https://api.github.com/user",
+            token
+        );
+        let summary = call_summary("shell_exec", &command, None);
+        assert!(!summary.contains(&token), "{}", summary);
+        assert!(summary.contains("[REDACTED]"));
+        assert!(summary.starts_with("shell_exec curl -H"));
+
+        let summary = call_summary("shell_exec", "deploy --token s3cr3tvalue --env prod", None);
+        assert_eq!(summary, "shell_exec deploy --token [REDACTED] --env prod");
+
+        let summary = call_summary("shell_exec", "API_KEY=abc123 ./run.sh", None);
+        assert_eq!(summary, "shell_exec API_KEY=[REDACTED] ./run.sh");
+
+        // A secret straddling the cap is redacted before truncation.
+        let long = format!("{} {}", "y".repeat(80), token);
+        let summary = call_summary("shell_exec", &long, None);
+        assert!(!summary.contains("ghp_"), "{}", summary);
+    }
+
+    #[test]
+    fn test_slow_call_canceller() {
+        let canceller = SlowCallCanceller::new();
+        assert!(!canceller.cancel_slow_call());
+        let token = CancellationToken::new();
+        canceller.clone().arm(token.clone());
+        assert!(canceller.has_slow_call());
+        assert!(canceller.cancel_slow_call());
+        assert!(token.is_cancelled());
+        assert!(!canceller.has_slow_call());
+        canceller.arm(CancellationToken::new());
+        canceller.disarm();
+        assert!(!canceller.cancel_slow_call());
+    }
+}
@@ -0,0 +1,546 @@
+//! Tool argument validation against each tool's declared JSON Schema.
+//!
+//! The agent checks the model's arguments before approval and execution, so a
+//! malformed call (missing required field, string where an integer is
+//! expected, value outside an `enum`) is rejected with an error that names the
+//! offending field, what was expected, and an example of a valid call. The
+//! model can then correct itself in one turn.
+//!
+//! Only the parts of JSON Schema that tool definitions actually use are
+//! enforced: `type` (single or list), `required`, `properties`,
+//! `additionalProperties: false`, `enum`, `minimum`/`maximum` and array
+//! `items`. Keywords outside that set (`anyOf`, `$ref`, formats, ...) are
+//! accepted without checking, so an unusual schema never blocks a call.
+//! Schemas with nothing to check — common for plugin and MCP-proxied tools —
+//! skip validation entirely; see [`is_trivial_schema`].
+//!
+//! With coercion enabled for a tool, benign mismatches are repaired instead
+//! of rejected: numeric strings become numbers and `"true"`/`"false"` become
+//! booleans.
+
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Violations listed individually before the rest are summarised.
+const MAX_LISTED_VIOLATIONS: usize = 5;
+
+/// One way in which a call's arguments break the tool's schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    /// Path of the offending field, e.g. `path`, `options.limit`, `files[2]`.
+    /// Empty for the arguments object itself.
+    pub field: String,
+    pub kind: ViolationKind,
+}
+
+/// What was wrong with a field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    /// A required field was not supplied.
+    Missing,
+    /// The value has the wrong JSON type.
+    WrongType { expected: String, found: String },
+    /// The value is not one of the allowed `enum` values.
+    NotAllowed { allowed: Vec<Value>, found: Value },
+    /// A number is outside `minimum`/`maximum`.
+    OutOfRange { bound: String, found: Value },
+    /// The schema forbids fields it does not declare.
+    Unknown,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field = if self.field.is_empty() {
+            "arguments".to_string()
+        } else {
+            format!("'{}'", self.field)
+        };
+        match &self.kind {
+            ViolationKind::Missing => write!(f, "{} is required but missing", field),
+            ViolationKind::WrongType { expected, found } => {
+                write!(f, "{} must be {}, got {}", field, expected, found)
+            }
+            ViolationKind::NotAllowed { allowed, found } => {
+                let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+                write!(
+                    f,
+                    "{} must be one of [{}], got {}",
+                    field,
+                    allowed.join(", "),
+                    found
+                )
+            }
+            ViolationKind::OutOfRange { bound, found } => {
+                write!(f, "{} must be {}, got {}", field, bound, found)
+            }
+            ViolationKind::Unknown => write!(f, "{} is not a recognised field", field),
+        }
+    }
+}
+
+/// Arguments rejected by a tool's schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgumentValidationError {
+    pub violations: Vec<SchemaViolation>,
+    /// Arguments that would satisfy the schema, for the model to copy.
+    pub example: Value,
+}
+
+impl ArgumentValidationError {
+    /// A message the model can act on: every violation, then an example call.
+    pub fn to_model_message(&self, tool_name: &str) -> String {
+        let mut lines: Vec<String> = self
+            .violations
+            .iter()
+            .take(MAX_LISTED_VIOLATIONS)
+            .map(|v| format!("- {}", v))
+            .collect();
+        if self.violations.len() > MAX_LISTED_VIOLATIONS {
+            lines.push(format!(
+                "- ...and {} more",
+                self.violations.len() - MAX_LISTED_VIOLATIONS
+            ));
+        }
+        format!(
+            "arguments do not match the tool's schema:\n{}\nExample of a valid call: {} {}",
+            lines.join("\n"),
+            tool_name,
+            self.example
+        )
+    }
+}
+
+/// Whether `schema` declares nothing worth checking.
+///
+/// True for a missing (`null`) or empty schema, a non-object schema, and an
+/// object schema with neither `properties` nor `required`.
+pub fn is_trivial_schema(schema: &Value) -> bool {
+    let Some(obj) = schema.as_object() else {
+        return true;
+    };
+    let has_properties = obj
+        .get("properties")
+        .and_then(Value::as_object)
+        .is_some_and(|p| !p.is_empty());
+    let has_required = obj
+        .get("required")
+        .and_then(Value::as_array)
+        .is_some_and(|r| !r.is_empty());
+    !has_properties && !has_required
+}
+
+/// Validate `args` against `schema`.
+///
+/// Returns `Ok(None)` when the arguments are valid as given, or
+/// `Ok(Some(coerced))` when `coerce` repaired them. Trivial schemas always
+/// pass. A `null` argument value is treated as an empty object.
+pub fn validate_arguments(
+    schema: &Value,
+    args: &Value,
+    coerce: bool,
+) -> Result<Option<Value>, ArgumentValidationError> {
+    if is_trivial_schema(schema) {
+        return Ok(None);
+    }
+    let mut value = if args.is_null() {
+        Value::Object(Map::new())
+    } else {
+        args.clone()
+    };
+    let mut validator = Validator {
+        coerce,
+        coerced: false,
+        violations: Vec::new(),
+    };
+    validator.check(schema, &mut value, "");
+    if !validator.violations.is_empty() {
+        return Err(ArgumentValidationError {
+            violations: validator.violations,
+            example: example_value(schema, ""),
+        });
+    }
+    Ok(validator.coerced.then_some(value))
+}
+
+struct Validator {
+    coerce: bool,
+    coerced: bool,
+    violations: Vec<SchemaViolation>,
+}
+
+impl Validator {
+    fn violation(&mut self, field: &str, kind: ViolationKind) {
+        self.violations.push(SchemaViolation {
+            field: field.to_string(),
+            kind,
+        });
+    }
+
+    fn check(&mut self, schema: &Value, value: &mut Value, path: &str) {
+        let Some(schema) = schema.as_object() else {
+            return;
+        };
+
+        let types = declared_types(schema);
+        if !types.is_empty() && !types.iter().any(|t| matches_type(t, value)) {
+            match self.try_coerce(&types, value) {
+                Some(coerced) => {
+                    *value = coerced;
+                    self.coerced = true;
+                }
+                None => {
+                    self.violation(
+                        path,
+                        ViolationKind::WrongType {
+                            expected: types.join(" or "),
+                            found: describe_value(value),
+                        },
+                    );
+                    return;
+                }
+            }
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+            && !allowed.contains(value)
+        {
+            self.violation(
+                path,
+                ViolationKind::NotAllowed {
+                    allowed: allowed.clone(),
+                    found: value.clone(),
+                },
+            );
+            return;
+        }
+
+        if let Some(n) = value.as_f64() {
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64)
+                && n < min
+            {
+                self.violation(
+                    path,
+                    ViolationKind::OutOfRange {
+                        bound: format!(">= {}", min),
+                        found: value.clone(),
+                    },
+                );
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64)
+                && n > max
+            {
+                self.violation(
+                    path,
+                    ViolationKind::OutOfRange {
+                        bound: format!("<= {}", max),
+                        found: value.clone(),
+                    },
+                );
+            }
+        }
+
+        match value {
+            Value::Object(fields) => self.check_object(schema, fields, path),
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (i, item) in items.iter_mut().enumerate() {
+                        self.check(item_schema, item, &format!("{}[{}]", path, i));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_object(
+        &mut self,
+        schema: &Map<String, Value>,
+        fields: &mut Map<String, Value>,
+        path: &str,
+    ) {
+        let empty = Map::new();
+        let properties = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for name in required.iter().filter_map(Value::as_str) {
+                if fields.get(name).is_none_or(Value::is_null) {
+                    self.violation(&join_path(path, name), ViolationKind::Missing);
+                }
+            }
+        }
+
+        let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+        for (name, field) in fields.iter_mut() {
+            let field_path = join_path(path, name);
+            match properties.get(name) {
+                // Optional fields sent as null mean "not provided".
+                Some(_) if field.is_null() => {}
+                Some(field_schema) => self.check(field_schema, field, &field_path),
+                None if closed => self.violation(&field_path, ViolationKind::Unknown),
+                None => {}
+            }
+        }
+    }
+
+    fn try_coerce(&self, types: &[&str], value: &Value) -> Option<Value> {
+        if !self.coerce {
+            return None;
+        }
+        let text = value.as_str()?.trim();
+        types.iter().find_map(|t| match *t {
+            "integer" => text.parse::<i64>().ok().map(Value::from),
+            "number" => text
+                .parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .map(Value::from),
+            "boolean" => match text.to_ascii_lowercase().as_str() {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+}
+
+fn declared_types(schema: &Map<String, Value>) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        // Unknown type names are not ours to enforce.
+        _ => true,
+    }
+}
+
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("boolean {}", b),
+        Value::Number(n) => format!("number {}", n),
+        Value::String(s) => {
+            let preview: String = s.chars().take(40).collect();
+            if preview.len() < s.len() {
+                format!("string \"{}...\"", preview)
+            } else {
+                format!("string \"{}\"", preview)
+            }
+        }
+        Value::Array(_) => "array".to_string(),
+        Value::Object(_) => "object".to_string(),
+    }
+}
+
+fn join_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// Build a value satisfying `schema`: objects get every required property,
+/// each filled from `examples`, `default`, the first `enum` value, or a
+/// placeholder for its type.
+fn example_value(schema: &Value, name: &str) -> Value {
+    let Some(schema) = schema.as_object() else {
+        return Value::Null;
+    };
+    if let Some(example) = schema
+        .get("examples")
+        .and_then(Value::as_array)
+        .and_then(|e| e.first())
+    {
+        return example.clone();
+    }
+    if let Some(default) = schema.get("default") {
+        return default.clone();
+    }
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|e| e.first())
+    {
+        return first.clone();
+    }
+
+    let ty = declared_types(schema)
+        .into_iter()
+        .find(|t| *t != "null")
+        .unwrap_or(if schema.contains_key("properties") {
+            "object"
+        } else {
+            "string"
+        });
+    match ty {
+        "object" => {
+            let mut out = Map::new();
+            let properties = schema.get("properties").and_then(Value::as_object);
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for field in required.iter().filter_map(Value::as_str) {
+                    let field_schema = properties
+                        .and_then(|p| p.get(field))
+                        .cloned()
+                        .unwrap_or(Value::Null);
+                    out.insert(field.to_string(), example_value(&field_schema, field));
+                }
+            }
+            Value::Object(out)
+        }
+        "array" => match schema.get("items") {
+            Some(items) => Value::Array(vec![example_value(items, name)]),
+            None => Value::Array(Vec::new()),
+        },
+        "integer" => Value::from(schema.get("minimum").and_then(Value::as_i64).unwrap_or(1)),
+        "number" => Value::from(schema.get("minimum").and_then(Value::as_f64).unwrap_or(1.0)),
+        "boolean" => Value::Bool(true),
+        _ if name.is_empty() => Value::String("...".to_string()),
+        _ => Value::String(format!("<{}>", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn read_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "description": "File to read" },
+                "limit": { "type": "integer", "minimum": 1 },
+                "follow": { "type": "boolean" },
+                "mode": { "type": "string", "enum": ["text", "hex"] }
+            },
+            "required": ["path"]
+        })
+    }
+
+    #[test]
+    fn test_valid_arguments_pass_unchanged() {
+        let args = json!({"path": "src/main.rs", "limit": 10, "mode": "hex"});
+        assert_eq!(validate_arguments(&read_schema(), &args, false), Ok(None));
+        // Optional fields sent as null are treated as absent.
+        let args = json!({"path": "a", "limit": null});
+        assert_eq!(validate_arguments(&read_schema(), &args, false), Ok(None));
+    }
+
+    #[test]
+    fn test_missing_required_field() {
+        let err = validate_arguments(&read_schema(), &json!({}), false).unwrap_err();
+        assert_eq!(
+            err.violations,
+            vec![SchemaViolation {
+                field: "path".into(),
+                kind: ViolationKind::Missing,
+            }]
+        );
+        assert_eq!(err.example, json!({"path": "<path>"}));
+        let message = err.to_model_message("file_read");
+        assert!(message.contains("'path' is required but missing"));
+        assert!(message.contains(r#"Example of a valid call: file_read {"path":"<path>"}"#));
+    }
+
+    #[test]
+    fn test_wrong_type_and_enum_are_reported_together() {
+        let args = json!({"path": "a", "limit": "ten", "mode": "binary"});
+        let err = validate_arguments(&read_schema(), &args, false).unwrap_err();
+        let message = err.to_model_message("file_read");
+        assert!(message.contains(r#"'limit' must be integer, got string "ten""#));
+        assert!(message.contains(r#"'mode' must be one of ["text", "hex"], got "binary""#));
+    }
+
+    #[test]
+    fn test_out_of_range_number() {
+        let err = validate_arguments(&read_schema(), &json!({"path": "a", "limit": 0}), false)
+            .unwrap_err();
+        assert_eq!(err.violations[0].to_string(), "'limit' must be >= 1, got 0");
+    }
+
+    #[test]
+    fn test_coercion_repairs_benign_mismatches() {
+        let args = json!({"path": "a", "limit": "25", "follow": "TRUE"});
+        assert!(validate_arguments(&read_schema(), &args, false).is_err());
+        let coerced = validate_arguments(&read_schema(), &args, true)
+            .unwrap()
+            .unwrap();
+        assert_eq!(coerced, json!({"path": "a", "limit": 25, "follow": true}));
+        // Strings that are not numbers are still rejected.
+        let args = json!({"path": "a", "limit": "lots"});
+        assert!(validate_arguments(&read_schema(), &args, true).is_err());
+    }
+
+    #[test]
+    fn test_nested_objects_and_arrays() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "edits": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "line": { "type": "integer" } },
+                        "required": ["line"],
+                        "additionalProperties": false
+                    }
+                }
+            },
+            "required": ["edits"]
+        });
+        let args = json!({"edits": [{"line": 1}, {"line": 2.5}, {"line": 3, "col": 4}]});
+        let err = validate_arguments(&schema, &args, false).unwrap_err();
+        let fields: Vec<&str> = err.violations.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(fields, vec!["edits[1].line", "edits[2].col"]);
+        assert_eq!(err.example, json!({"edits": [{"line": 1}]}));
+    }
+
+    #[test]
+    fn test_trivial_schemas_skip_validation() {
+        for schema in [
+            Value::Null,
+            json!({}),
+            json!({"type": "object"}),
+            json!({"type": "object", "properties": {}}),
+        ] {
+            assert!(is_trivial_schema(&schema));
+            assert_eq!(
+                validate_arguments(&schema, &json!("anything"), false),
+                Ok(None)
+            );
+        }
+        assert!(!is_trivial_schema(&read_schema()));
+    }
+
+    #[test]
+    fn test_unsupported_keywords_are_not_enforced() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "target": { "anyOf": [{ "type": "string" }, { "type": "integer" }] },
+                "when": { "type": "string", "format": "date-time" }
+            },
+            "required": ["target"]
+        });
+        let args = json!({"target": [1, 2], "when": "tomorrow"});
+        assert_eq!(validate_arguments(&schema, &args, false), Ok(None));
+    }
+}
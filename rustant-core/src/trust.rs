@@ -0,0 +1,628 @@
+//! Contextual trust scores for adaptive approval.
+//!
+//! Each (tool, workspace) pair carries its own score in `[0, 1]`. Approvals
+//! raise it, denials and guardian blocks cut it sharply, failures nudge it down,
+//! and it decays exponentially while the tool goes unused. Scores only ever
+//! auto-approve operations at or below [`TRUST_RISK_CEILING`]; execute, network
+//! and destructive actions always go through the normal approval path.
+//!
+//! The ledger is persisted as JSON (see [`default_trust_path`]) so trust
+//! survives restarts.
+
+use crate::config::AdaptiveTrustConfig;
+use crate::types::RiskLevel;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// File name of the persisted ledger inside the rustant data directory.
+pub const TRUST_FILE: &str = "trust.json";
+
+/// Highest risk level trust may ever auto-approve, whatever the configuration.
+pub const TRUST_RISK_CEILING: RiskLevel = RiskLevel::Write;
+
+/// Events kept per entry for explaining a score.
+const MAX_EVENTS: usize = 20;
+
+/// Decay losses smaller than this are applied silently rather than logged.
+const MIN_LOGGED_DECAY: f64 = 0.01;
+
+/// Default location of the persisted trust ledger.
+pub fn default_trust_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("dev", "rustant", "rustant")
+        .map(|d| d.data_dir().join(TRUST_FILE))
+}
+
+/// Errors reading or writing the trust ledger.
+#[derive(Debug, thiserror::Error)]
+pub enum TrustError {
+    #[error("Failed to access trust store {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Corrupt trust store {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+}
+
+/// What happened to move a trust score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustEventKind {
+    /// The user approved the tool.
+    Approved,
+    /// The user denied the tool.
+    Denied,
+    /// The safety guardian blocked the call outright.
+    Blocked,
+    /// The tool ran and failed.
+    Failed,
+    /// Trust eroded while the tool was unused.
+    Decayed,
+}
+
+impl std::fmt::Display for TrustEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrustEventKind::Approved => write!(f, "approved"),
+            TrustEventKind::Denied => write!(f, "denied"),
+            TrustEventKind::Blocked => write!(f, "blocked"),
+            TrustEventKind::Failed => write!(f, "failed"),
+            TrustEventKind::Decayed => write!(f, "decayed"),
+        }
+    }
+}
+
+/// A recorded change to a trust score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustEvent {
+    pub kind: TrustEventKind,
+    /// Signed change applied to the score.
+    pub delta: f64,
+    pub at: DateTime<Utc>,
+}
+
+/// Tuning for how scores move. Built from [`AdaptiveTrustConfig`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrustPolicy {
+    /// Score at or above which a tool is auto-approved.
+    pub auto_approve_score: f64,
+    /// Score gained per approval.
+    pub approval_gain: f64,
+    /// Days for an unused score to halve (0 disables decay).
+    pub half_life_days: f64,
+    /// Score lost per denial.
+    pub denial_penalty: f64,
+    /// Score lost when the guardian blocks a call.
+    pub block_penalty: f64,
+    /// Score lost per failed execution.
+    pub failure_penalty: f64,
+    /// Highest risk auto-approved, clamped to [`TRUST_RISK_CEILING`].
+    pub max_auto_approve_risk: RiskLevel,
+}
+
+impl TrustPolicy {
+    pub fn from_config(config: &AdaptiveTrustConfig) -> Self {
+        let auto_approve_score = config.auto_approve_score.clamp(0.0, 1.0);
+        Self {
+            auto_approve_score,
+            // Reaching the threshold takes exactly `trust_escalation_threshold` approvals.
+            approval_gain: auto_approve_score / config.trust_escalation_threshold.max(1) as f64,
+            half_life_days: config.decay_half_life_days.max(0.0),
+            denial_penalty: config.denial_penalty.max(0.0),
+            block_penalty: config.block_penalty.max(0.0),
+            failure_penalty: config.failure_penalty.max(0.0),
+            max_auto_approve_risk: config.max_auto_approve_risk.min(TRUST_RISK_CEILING),
+        }
+    }
+
+    /// Multiplier applied to a score after `elapsed` without use.
+    pub fn decay_factor(&self, elapsed: Duration) -> f64 {
+        if self.half_life_days <= 0.0 || elapsed <= Duration::zero() {
+            return 1.0;
+        }
+        let days = elapsed.num_seconds() as f64 / 86_400.0;
+        0.5_f64.powf(days / self.half_life_days)
+    }
+
+    /// Whether trust is ever allowed to auto-approve this risk level.
+    pub fn within_ceiling(&self, risk: RiskLevel) -> bool {
+        risk <= self.max_auto_approve_risk
+    }
+
+    fn delta(&self, kind: TrustEventKind) -> f64 {
+        match kind {
+            TrustEventKind::Approved => self.approval_gain,
+            TrustEventKind::Denied => -self.denial_penalty,
+            TrustEventKind::Blocked => -self.block_penalty,
+            TrustEventKind::Failed => -self.failure_penalty,
+            TrustEventKind::Decayed => 0.0,
+        }
+    }
+}
+
+impl Default for TrustPolicy {
+    fn default() -> Self {
+        Self::from_config(&AdaptiveTrustConfig::default())
+    }
+}
+
+/// Trust held by one tool in one workspace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustEntry {
+    pub tool: String,
+    pub workspace: String,
+    /// Score as of `last_used`; see [`TrustEntry::score_at`] for the decayed value.
+    pub score: f64,
+    pub last_used: DateTime<Utc>,
+    #[serde(default)]
+    pub approvals: usize,
+    #[serde(default)]
+    pub denials: usize,
+    /// Most recent score changes, oldest first.
+    #[serde(default)]
+    pub events: Vec<TrustEvent>,
+}
+
+impl TrustEntry {
+    fn new(tool: &str, workspace: &str, now: DateTime<Utc>) -> Self {
+        Self {
+            tool: tool.to_string(),
+            workspace: workspace.to_string(),
+            score: 0.0,
+            last_used: now,
+            approvals: 0,
+            denials: 0,
+            events: Vec::new(),
+        }
+    }
+
+    /// Score after decaying for the time since the tool was last used.
+    pub fn score_at(&self, policy: &TrustPolicy, now: DateTime<Utc>) -> f64 {
+        self.score * policy.decay_factor(now - self.last_used)
+    }
+
+    /// Events with the largest effect on the score, biggest first.
+    pub fn influential_events(&self, limit: usize) -> Vec<&TrustEvent> {
+        let mut events: Vec<&TrustEvent> = self.events.iter().collect();
+        events.sort_by(|a, b| {
+            b.delta
+                .abs()
+                .total_cmp(&a.delta.abs())
+                .then(b.at.cmp(&a.at))
+        });
+        events.truncate(limit);
+        events
+    }
+
+    fn push_event(&mut self, kind: TrustEventKind, delta: f64, at: DateTime<Utc>) {
+        self.events.push(TrustEvent { kind, delta, at });
+        if self.events.len() > MAX_EVENTS {
+            let excess = self.events.len() - MAX_EVENTS;
+            self.events.drain(..excess);
+        }
+    }
+
+    /// Fold elapsed decay into the stored score and mark the tool as used now.
+    fn touch(&mut self, policy: &TrustPolicy, now: DateTime<Utc>) {
+        let decayed = self.score_at(policy, now);
+        let loss = self.score - decayed;
+        if loss >= MIN_LOGGED_DECAY {
+            self.push_event(TrustEventKind::Decayed, -loss, now);
+        }
+        self.score = decayed;
+        if now > self.last_used {
+            self.last_used = now;
+        }
+    }
+}
+
+/// Which entries a reset applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrustScope {
+    All,
+    Tool(String),
+    Workspace(String),
+}
+
+impl TrustScope {
+    /// Parse a reset target: `all`, `workspace [path]` (defaulting to
+    /// `current_workspace`), or a tool name.
+    pub fn parse(target: &str, current_workspace: &str) -> Option<Self> {
+        let target = target.trim();
+        let (head, rest) = target
+            .split_once(char::is_whitespace)
+            .map(|(h, r)| (h, r.trim()))
+            .unwrap_or((target, ""));
+        match head {
+            "" => None,
+            "all" if rest.is_empty() => Some(TrustScope::All),
+            "workspace" if rest.is_empty() => {
+                Some(TrustScope::Workspace(current_workspace.to_string()))
+            }
+            "workspace" => Some(TrustScope::Workspace(rest.to_string())),
+            tool if rest.is_empty() => Some(TrustScope::Tool(tool.to_string())),
+            _ => None,
+        }
+    }
+
+    pub fn matches(&self, entry: &TrustEntry) -> bool {
+        match self {
+            TrustScope::All => true,
+            TrustScope::Tool(tool) => entry.tool == *tool,
+            TrustScope::Workspace(workspace) => entry.workspace == *workspace,
+        }
+    }
+}
+
+/// All trust entries, keyed by (tool, workspace).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrustLedger {
+    #[serde(default)]
+    entries: Vec<TrustEntry>,
+}
+
+impl TrustLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a ledger from disk. A missing file yields an empty ledger.
+    pub fn load(path: &Path) -> Result<Self, TrustError> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(source) => {
+                return Err(TrustError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                });
+            }
+        };
+        serde_json::from_str(&content).map_err(|e| TrustError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Write the ledger atomically (temp file + rename).
+    pub fn save(&self, path: &Path) -> Result<(), TrustError> {
+        let io_err = |source| TrustError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_err)?;
+        }
+        let json = serde_json::to_string_pretty(self).expect("trust ledger serializes");
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(io_err)?;
+        std::fs::rename(&tmp, path).map_err(io_err)
+    }
+
+    pub fn entries(&self) -> &[TrustEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, tool: &str, workspace: &str) -> Option<&TrustEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.tool == tool && e.workspace == workspace)
+    }
+
+    /// Current (decayed) score for a tool in a workspace; 0 if never seen.
+    pub fn score(
+        &self,
+        tool: &str,
+        workspace: &str,
+        policy: &TrustPolicy,
+        now: DateTime<Utc>,
+    ) -> f64 {
+        self.get(tool, workspace)
+            .map(|e| e.score_at(policy, now))
+            .unwrap_or(0.0)
+    }
+
+    /// Apply an event to the (tool, workspace) score and return the new score.
+    pub fn record(
+        &mut self,
+        tool: &str,
+        workspace: &str,
+        kind: TrustEventKind,
+        policy: &TrustPolicy,
+        now: DateTime<Utc>,
+    ) -> f64 {
+        let entry = self.entry_mut(tool, workspace, now);
+        entry.touch(policy, now);
+        match kind {
+            TrustEventKind::Approved => entry.approvals += 1,
+            TrustEventKind::Denied => entry.denials += 1,
+            _ => {}
+        }
+        let before = entry.score;
+        entry.score = (entry.score + policy.delta(kind)).clamp(0.0, 1.0);
+        let applied = entry.score - before;
+        // Penalties are always logged, even when the score was already at zero.
+        if applied != 0.0 || matches!(kind, TrustEventKind::Denied | TrustEventKind::Blocked) {
+            entry.push_event(kind, applied, now);
+        }
+        entry.score
+    }
+
+    /// Note a successful use: no score change, but it stops the decay clock.
+    pub fn record_use(
+        &mut self,
+        tool: &str,
+        workspace: &str,
+        policy: &TrustPolicy,
+        now: DateTime<Utc>,
+    ) {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|e| e.tool == tool && e.workspace == workspace)
+        {
+            entry.touch(policy, now);
+        }
+    }
+
+    /// Remove every entry matching `scope`, returning how many were removed.
+    pub fn reset(&mut self, scope: &TrustScope) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|e| !scope.matches(e));
+        before - self.entries.len()
+    }
+
+    /// Human-readable listing of scores and their most influential events.
+    pub fn report(&self, policy: &TrustPolicy, scope: &TrustScope, now: DateTime<Utc>) -> String {
+        let mut entries: Vec<&TrustEntry> =
+            self.entries.iter().filter(|e| scope.matches(e)).collect();
+        if entries.is_empty() {
+            return "No trust history recorded.".to_string();
+        }
+        entries.sort_by(|a, b| a.workspace.cmp(&b.workspace).then(a.tool.cmp(&b.tool)));
+
+        let mut out = format!(
+            "Trust scores (auto-approve at {:.2}, {} risk and below, half-life {} days):\n",
+            policy.auto_approve_score, policy.max_auto_approve_risk, policy.half_life_days
+        );
+        let mut workspace = None;
+        for entry in entries {
+            if workspace != Some(&entry.workspace) {
+                let _ = writeln!(out, "  {}", entry.workspace);
+                workspace = Some(&entry.workspace);
+            }
+            let score = entry.score_at(policy, now);
+            let marker = if score + f64::EPSILON >= policy.auto_approve_score {
+                " (auto-approve)"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                out,
+                "    {:<20} {:.2}{}  approved {} | denied {} | last used {}",
+                entry.tool,
+                score,
+                marker,
+                entry.approvals,
+                entry.denials,
+                entry.last_used.format("%Y-%m-%d")
+            );
+            for event in entry.influential_events(3) {
+                let _ = writeln!(
+                    out,
+                    "      {:+.2} {} on {}",
+                    event.delta,
+                    event.kind,
+                    event.at.format("%Y-%m-%d %H:%M")
+                );
+            }
+        }
+        out.truncate(out.trim_end().len());
+        out
+    }
+
+    fn entry_mut(&mut self, tool: &str, workspace: &str, now: DateTime<Utc>) -> &mut TrustEntry {
+        let index = match self
+            .entries
+            .iter()
+            .position(|e| e.tool == tool && e.workspace == workspace)
+        {
+            Some(index) => index,
+            None => {
+                self.entries.push(TrustEntry::new(tool, workspace, now));
+                self.entries.len() - 1
+            }
+        };
+        &mut self.entries[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> TrustPolicy {
+        TrustPolicy::from_config(&AdaptiveTrustConfig {
+            trust_escalation_threshold: 4,
+            auto_approve_score: 0.8,
+            decay_half_life_days: 10.0,
+            ..Default::default()
+        })
+    }
+
+    fn t0() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_decay_halves_per_half_life() {
+        let policy = policy();
+        assert_eq!(policy.decay_factor(Duration::zero()), 1.0);
+        assert!((policy.decay_factor(Duration::days(10)) - 0.5).abs() < 1e-9);
+        assert!((policy.decay_factor(Duration::days(20)) - 0.25).abs() < 1e-9);
+        assert!((policy.decay_factor(Duration::days(5)) - 0.5_f64.sqrt()).abs() < 1e-9);
+
+        let no_decay = TrustPolicy {
+            half_life_days: 0.0,
+            ..policy
+        };
+        assert_eq!(no_decay.decay_factor(Duration::days(365)), 1.0);
+    }
+
+    #[test]
+    fn test_unused_trust_erodes_and_use_stops_the_clock() {
+        let policy = policy();
+        let mut ledger = TrustLedger::new();
+        for _ in 0..4 {
+            ledger.record("file_write", "/ws", TrustEventKind::Approved, &policy, t0());
+        }
+        assert!((ledger.score("file_write", "/ws", &policy, t0()) - 0.8).abs() < 1e-9);
+
+        // A month unused: three half-lives.
+        let later = t0() + Duration::days(30);
+        assert!((ledger.score("file_write", "/ws", &policy, later) - 0.1).abs() < 1e-9);
+
+        // Using it folds the decay in, logs it, and restarts the clock.
+        ledger.record_use("file_write", "/ws", &policy, later);
+        let entry = ledger.get("file_write", "/ws").unwrap();
+        assert!((entry.score - 0.1).abs() < 1e-9);
+        assert_eq!(entry.last_used, later);
+        let decay = entry.events.last().unwrap();
+        assert_eq!(decay.kind, TrustEventKind::Decayed);
+        assert!((decay.delta + 0.7).abs() < 1e-9);
+        assert!((ledger.score("file_write", "/ws", &policy, later) - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_scores_are_per_tool_and_workspace() {
+        let policy = policy();
+        let mut ledger = TrustLedger::new();
+        ledger.record("file_write", "/a", TrustEventKind::Approved, &policy, t0());
+        assert!(ledger.score("file_write", "/a", &policy, t0()) > 0.0);
+        assert_eq!(ledger.score("file_write", "/b", &policy, t0()), 0.0);
+        assert_eq!(ledger.score("file_patch", "/a", &policy, t0()), 0.0);
+    }
+
+    #[test]
+    fn test_penalties_are_sharp_and_clamped() {
+        let policy = policy();
+        let mut ledger = TrustLedger::new();
+        for _ in 0..4 {
+            ledger.record("git_commit", "/ws", TrustEventKind::Approved, &policy, t0());
+        }
+        let after_denial =
+            ledger.record("git_commit", "/ws", TrustEventKind::Denied, &policy, t0());
+        assert!((after_denial - 0.3).abs() < 1e-9);
+        let after_block =
+            ledger.record("git_commit", "/ws", TrustEventKind::Blocked, &policy, t0());
+        assert_eq!(after_block, 0.0);
+
+        // Penalties at zero are still logged, and they dominate the explanation.
+        let entry = ledger.get("git_commit", "/ws").unwrap();
+        assert_eq!(entry.denials, 1);
+        let top = entry.influential_events(1);
+        assert_eq!(top[0].kind, TrustEventKind::Denied);
+        ledger.record("git_commit", "/ws", TrustEventKind::Blocked, &policy, t0());
+        assert_eq!(ledger.get("git_commit", "/ws").unwrap().events.len(), 7);
+    }
+
+    #[test]
+    fn test_ceiling_cannot_be_raised_by_config() {
+        let policy = TrustPolicy::from_config(&AdaptiveTrustConfig {
+            max_auto_approve_risk: RiskLevel::Destructive,
+            ..Default::default()
+        });
+        assert_eq!(policy.max_auto_approve_risk, TRUST_RISK_CEILING);
+        assert!(policy.within_ceiling(RiskLevel::Write));
+        assert!(!policy.within_ceiling(RiskLevel::Execute));
+        assert!(!policy.within_ceiling(RiskLevel::Network));
+        assert!(!policy.within_ceiling(RiskLevel::Destructive));
+
+        let stricter = TrustPolicy::from_config(&AdaptiveTrustConfig {
+            max_auto_approve_risk: RiskLevel::ReadOnly,
+            ..Default::default()
+        });
+        assert!(!stricter.within_ceiling(RiskLevel::Write));
+    }
+
+    #[test]
+    fn test_reset_scopes() {
+        let policy = policy();
+        let mut ledger = TrustLedger::new();
+        for (tool, ws) in [("a", "/x"), ("b", "/x"), ("a", "/y")] {
+            ledger.record(tool, ws, TrustEventKind::Approved, &policy, t0());
+        }
+        assert_eq!(ledger.reset(&TrustScope::Tool("a".into())), 2);
+        assert_eq!(ledger.entries().len(), 1);
+        assert_eq!(ledger.reset(&TrustScope::Workspace("/y".into())), 0);
+        assert_eq!(ledger.reset(&TrustScope::All), 1);
+        assert!(ledger.is_empty());
+    }
+
+    #[test]
+    fn test_scope_parse() {
+        assert_eq!(TrustScope::parse("all", "/ws"), Some(TrustScope::All));
+        assert_eq!(
+            TrustScope::parse("workspace", "/ws"),
+            Some(TrustScope::Workspace("/ws".into()))
+        );
+        assert_eq!(
+            TrustScope::parse("workspace /other", "/ws"),
+            Some(TrustScope::Workspace("/other".into()))
+        );
+        assert_eq!(
+            TrustScope::parse(" shell_exec ", "/ws"),
+            Some(TrustScope::Tool("shell_exec".into()))
+        );
+        assert_eq!(TrustScope::parse("", "/ws"), None);
+        assert_eq!(TrustScope::parse("two tools", "/ws"), None);
+    }
+
+    #[test]
+    fn test_persistence_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(TRUST_FILE);
+        assert!(TrustLedger::load(&path).unwrap().is_empty());
+
+        let policy = policy();
+        let mut ledger = TrustLedger::new();
+        ledger.record("file_write", "/ws", TrustEventKind::Approved, &policy, t0());
+        ledger.record("file_write", "/ws", TrustEventKind::Denied, &policy, t0());
+        ledger.save(&path).unwrap();
+        assert_eq!(TrustLedger::load(&path).unwrap(), ledger);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            TrustLedger::load(&path),
+            Err(TrustError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn test_report_lists_scores_and_influential_events() {
+        let policy = policy();
+        let mut ledger = TrustLedger::new();
+        for _ in 0..4 {
+            ledger.record("file_write", "/ws", TrustEventKind::Approved, &policy, t0());
+        }
+        ledger.record("shell_exec", "/ws", TrustEventKind::Denied, &policy, t0());
+
+        let report = ledger.report(&policy, &TrustScope::All, t0());
+        assert!(report.contains("/ws"));
+        assert!(report.contains("file_write"));
+        assert!(report.contains("0.80 (auto-approve)"));
+        assert!(report.contains("+0.20 approved"));
+        assert!(report.contains("denied 1"));
+
+        let filtered = ledger.report(&policy, &TrustScope::Tool("nope".into()), t0());
+        assert_eq!(filtered, "No trust history recorded.");
+    }
+}
@@ -50,6 +50,14 @@ pub enum Content {
     MultiPart {
         parts: Vec<Content>,
     },
+    /// A binary tool output referenced by handle. Only the handle is kept in
+    /// history; `image` is filled in when a request for a vision-capable
+    /// provider is assembled, and is never serialized.
+    Attachment {
+        handle: AttachmentHandle,
+        #[serde(skip)]
+        image: Option<ImageSource>,
+    },
 }
 
 impl Content {
@@ -84,6 +92,14 @@ impl Content {
         }
     }
 
+    /// Create an attachment reference content.
+    pub fn attachment(handle: AttachmentHandle) -> Self {
+        Content::Attachment {
+            handle,
+            image: None,
+        }
+    }
+
     /// Returns the text representation of this content.
     pub fn as_text(&self) -> Option<&str> {
         match self {
@@ -155,6 +171,7 @@ impl Message {
             } => name.len() + arguments.to_string().len(),
             Content::ToolResult { output, .. } => output.len(),
             Content::MultiPart { parts } => parts.iter().map(content_char_len).sum(),
+            Content::Attachment { handle, .. } => handle.reference().len(),
         }
     }
 }
@@ -168,9 +185,72 @@ fn content_char_len(c: &Content) -> usize {
         } => name.len() + arguments.to_string().len(),
         Content::ToolResult { output, .. } => output.len(),
         Content::MultiPart { parts } => parts.iter().map(content_char_len).sum(),
+        Content::Attachment { handle, .. } => handle.reference().len(),
+    }
+}
+
+/// Typed reference to a binary tool output held in the attachment store.
+///
+/// This is all the model ever sees of an attachment; tools and providers
+/// resolve the id back to the stored bytes when they need them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttachmentHandle {
+    pub id: String,
+    pub mime_type: String,
+    /// Size of the stored bytes.
+    pub size: u64,
+    pub description: String,
+    /// Short textual stand-in for the content, such as image dimensions or
+    /// the first line of extracted text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail_text: Option<String>,
+    /// Pixel width and height, for images whose header could be read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<(u32, u32)>,
+}
+
+impl AttachmentHandle {
+    /// Whether the attachment is a raster image a vision model can look at.
+    pub fn is_image(&self) -> bool {
+        matches!(
+            self.mime_type.as_str(),
+            "image/png" | "image/jpeg" | "image/gif" | "image/webp"
+        )
+    }
+
+    /// One-line reference placed in the context instead of the bytes.
+    pub fn reference(&self) -> String {
+        let mut line = format!(
+            "[attachment {}: {}, {} — {}",
+            self.id,
+            self.mime_type,
+            format_size(self.size),
+            self.description
+        );
+        if let Some(thumbnail) = &self.thumbnail_text {
+            line.push_str(&format!(" ({})", thumbnail));
+        }
+        line.push(']');
+        line
     }
 }
 
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Image bytes resolved from an attachment for a provider request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageSource {
+    pub media_type: String,
+    /// Base64-encoded image data.
+    pub data: String,
+}
+
 /// A definition describing a tool for the LLM.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolDefinition {
@@ -214,6 +294,13 @@ pub struct ToolOutput {
     pub artifacts: Vec<Artifact>,
     #[serde(default)]
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Binary outputs, moved into the attachment store by the agent before
+    /// the output reaches the context.
+    #[serde(skip)]
+    pub attachments: Vec<Attachment>,
+    /// Structured failure, for outputs that report an error in-band.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ToolFailure>,
 }
 
 impl ToolOutput {
@@ -223,6 +310,8 @@ impl ToolOutput {
             content: content.into(),
             artifacts: Vec::new(),
             metadata: HashMap::new(),
+            attachments: Vec::new(),
+            error: None,
         }
     }
 
@@ -235,21 +324,319 @@ impl ToolOutput {
         output
     }
 
+    /// Create an error output with a structured failure the agent loop
+    /// can act on (retry, surface, or route to approval).
+    pub fn failure(message: impl Into<String>, failure: ToolFailure) -> Self {
+        let mut output = Self::error(message);
+        output.error = Some(failure);
+        output
+    }
+
     /// Add an artifact to this output.
     pub fn with_artifact(mut self, artifact: Artifact) -> Self {
         self.artifacts.push(artifact);
         self
     }
+
+    /// Add a binary attachment to this output.
+    pub fn with_attachment(mut self, attachment: Attachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+}
+
+/// What kind of failure a tool hit, which decides how the agent responds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolErrorKind {
+    NotFound,
+    PermissionDenied,
+    Timeout,
+    RateLimited,
+    InvalidArguments,
+    TransientNetwork,
+    Conflict,
+    Internal,
+}
+
+impl ToolErrorKind {
+    /// Whether the same call may succeed if simply retried later.
+    pub fn is_transient(self) -> bool {
+        matches!(
+            self,
+            Self::Timeout | Self::RateLimited | Self::TransientNetwork
+        )
+    }
+
+    /// Snake-case name, as used in metrics and messages.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NotFound => "not_found",
+            Self::PermissionDenied => "permission_denied",
+            Self::Timeout => "timeout",
+            Self::RateLimited => "rate_limited",
+            Self::InvalidArguments => "invalid_arguments",
+            Self::TransientNetwork => "transient_network",
+            Self::Conflict => "conflict",
+            Self::Internal => "internal",
+        }
+    }
+
+    /// Guess the kind of a plain-text error message, for tools that do not
+    /// report one.
+    pub fn classify(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+        if has(&["rate limit", "too many requests", " 429"]) {
+            Self::RateLimited
+        } else if has(&["timed out", "timeout", "deadline exceeded"]) {
+            Self::Timeout
+        } else if has(&[
+            "connection refused",
+            "connection reset",
+            "connection closed",
+            "broken pipe",
+            "dns error",
+            "temporarily unavailable",
+            "network is unreachable",
+            " 502",
+            " 503",
+            " 504",
+        ]) {
+            Self::TransientNetwork
+        } else if has(&[
+            "permission denied",
+            "access denied",
+            "forbidden",
+            " 401",
+            " 403",
+        ]) {
+            Self::PermissionDenied
+        } else if has(&[
+            "no such file",
+            "not found",
+            "does not exist",
+            "unknown revision",
+            " 404",
+        ]) {
+            Self::NotFound
+        } else if has(&[
+            "already exists",
+            "conflict",
+            "merge conflict",
+            " 409",
+            " 412",
+        ]) {
+            Self::Conflict
+        } else if has(&["invalid argument", "invalid input", "missing required"]) {
+            Self::InvalidArguments
+        } else {
+            Self::Internal
+        }
+    }
+
+    fn default_remediation(self) -> &'static str {
+        match self {
+            Self::NotFound => {
+                "Check the name or path (list or search first); repeating the same call will fail again."
+            }
+            Self::PermissionDenied => {
+                "Choose a target the agent may access, or ask the user to grant access."
+            }
+            Self::Timeout => "Narrow the request, or split it into smaller steps.",
+            Self::RateLimited => "Wait before calling this service again.",
+            Self::InvalidArguments => "Fix the arguments to match the tool's parameter schema.",
+            Self::TransientNetwork => "The network failed; try again shortly.",
+            Self::Conflict => "Re-read the current state and reconcile before retrying.",
+            Self::Internal => "The tool failed unexpectedly; try a different approach.",
+        }
+    }
+}
+
+impl std::fmt::Display for ToolErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A structured tool failure: its kind, an optional retry hint, and what to
+/// do about it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolFailure {
+    pub kind: ToolErrorKind,
+    /// Seconds to wait before retrying, when the tool was told.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
+    /// Suggested next step for the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+}
+
+impl ToolFailure {
+    pub fn new(kind: ToolErrorKind) -> Self {
+        Self {
+            kind,
+            retry_after_secs: None,
+            remediation: None,
+        }
+    }
+
+    /// Set the retry-after hint.
+    pub fn with_retry_after(mut self, secs: u64) -> Self {
+        self.retry_after_secs = Some(secs);
+        self
+    }
+
+    /// Set the remediation suggestion.
+    pub fn with_remediation(mut self, remediation: impl Into<String>) -> Self {
+        self.remediation = Some(remediation.into());
+        self
+    }
+
+    /// The remediation suggestion, or the default for the kind.
+    pub fn remediation_text(&self) -> &str {
+        self.remediation
+            .as_deref()
+            .unwrap_or_else(|| self.kind.default_remediation())
+    }
+
+    /// Classify an I/O error.
+    pub fn from_io(error: &std::io::Error) -> Self {
+        use std::io::ErrorKind as Io;
+        Self::new(match error.kind() {
+            Io::NotFound => ToolErrorKind::NotFound,
+            Io::PermissionDenied | Io::ReadOnlyFilesystem => ToolErrorKind::PermissionDenied,
+            Io::AlreadyExists | Io::DirectoryNotEmpty => ToolErrorKind::Conflict,
+            Io::TimedOut | Io::WouldBlock => ToolErrorKind::Timeout,
+            Io::ConnectionRefused
+            | Io::ConnectionReset
+            | Io::ConnectionAborted
+            | Io::NotConnected
+            | Io::BrokenPipe
+            | Io::Interrupted
+            | Io::HostUnreachable
+            | Io::NetworkUnreachable => ToolErrorKind::TransientNetwork,
+            Io::InvalidInput | Io::InvalidFilename | Io::NotADirectory | Io::IsADirectory => {
+                ToolErrorKind::InvalidArguments
+            }
+            _ => ToolErrorKind::classify(&error.to_string()),
+        })
+    }
+
+    /// Classify an HTTP error status; `None` for success statuses.
+    pub fn from_http_status(status: u16, retry_after_secs: Option<u64>) -> Option<Self> {
+        let kind = match status {
+            200..=399 => return None,
+            401 | 403 => ToolErrorKind::PermissionDenied,
+            404 | 410 => ToolErrorKind::NotFound,
+            408 => ToolErrorKind::Timeout,
+            409 | 412 => ToolErrorKind::Conflict,
+            429 => ToolErrorKind::RateLimited,
+            400 | 405 | 406 | 411 | 413..=422 => ToolErrorKind::InvalidArguments,
+            502..=504 => ToolErrorKind::TransientNetwork,
+            _ => ToolErrorKind::Internal,
+        };
+        let mut failure = Self::new(kind);
+        failure.retry_after_secs = retry_after_secs;
+        Some(failure)
+    }
+
+    /// Classify an HTTP client error.
+    pub fn from_reqwest(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Self::new(ToolErrorKind::Timeout)
+        } else if error.is_connect() || error.is_request() {
+            Self::new(ToolErrorKind::TransientNetwork)
+        } else if let Some(status) = error.status()
+            && let Some(failure) = Self::from_http_status(status.as_u16(), None)
+        {
+            failure
+        } else if error.is_builder() {
+            Self::new(ToolErrorKind::InvalidArguments)
+        } else {
+            Self::new(ToolErrorKind::classify(&error.to_string()))
+        }
+    }
+}
+
+/// A binary output produced by a tool, such as a screenshot or exported chart.
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub mime_type: String,
+    pub description: String,
+    pub thumbnail_text: Option<String>,
+    pub data: AttachmentData,
+}
+
+/// Where an attachment's bytes come from.
+#[derive(Debug, Clone)]
+pub enum AttachmentData {
+    Bytes(Vec<u8>),
+    /// A file the tool wrote; it is copied into the store.
+    File(PathBuf),
+}
+
+impl Attachment {
+    /// An attachment from in-memory bytes.
+    pub fn bytes(
+        mime_type: impl Into<String>,
+        description: impl Into<String>,
+        data: Vec<u8>,
+    ) -> Self {
+        Self {
+            mime_type: mime_type.into(),
+            description: description.into(),
+            thumbnail_text: None,
+            data: AttachmentData::Bytes(data),
+        }
+    }
+
+    /// An attachment from a file on disk.
+    pub fn file(
+        mime_type: impl Into<String>,
+        description: impl Into<String>,
+        path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            mime_type: mime_type.into(),
+            description: description.into(),
+            thumbnail_text: None,
+            data: AttachmentData::File(path.into()),
+        }
+    }
+
+    /// Set the short textual stand-in shown with the handle.
+    pub fn with_thumbnail_text(mut self, text: impl Into<String>) -> Self {
+        self.thumbnail_text = Some(text.into());
+        self
+    }
 }
 
 /// An artifact produced by a tool (file created, data generated, etc.).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Artifact {
-    FileCreated { path: PathBuf },
-    FileModified { path: PathBuf, diff: String },
-    FileDeleted { path: PathBuf },
-    Data { mime_type: String, data: String },
+    FileCreated {
+        path: PathBuf,
+    },
+    FileModified {
+        path: PathBuf,
+        diff: String,
+    },
+    FileDeleted {
+        path: PathBuf,
+    },
+    Data {
+        mime_type: String,
+        data: String,
+    },
+    /// Content pushed to a canvas; kept whole so it can be re-pushed later.
+    CanvasItem {
+        id: String,
+        target: String,
+        content_type: String,
+        content: String,
+    },
 }
 
 /// Progress update from a running tool execution.
@@ -595,6 +982,10 @@ impl TaskClassification {
             || lower.contains("paper to notebook")
             || lower.contains("bibtex")
             || lower.contains("preprint")
+            || lower.contains("citation archive")
+            || lower.contains("archive citation")
+            || lower.contains("archive the citation")
+            || lower.contains("cited source")
             || (lower.contains("paper")
                 && (lower.contains("search")
                     || lower.contains("find")
@@ -622,6 +1013,8 @@ impl TaskClassification {
         }
         if lower.contains("experiment")
             || lower.contains("hypothesis")
+            || lower.contains("model registry")
+            || lower.contains("model card")
             || lower.contains("test result")
             || lower.contains("lab ")
         {
@@ -715,6 +1108,12 @@ pub struct AgentState {
     /// Cached task classification, computed once at task start.
     #[serde(default)]
     pub task_classification: Option<TaskClassification>,
+    /// Calls this task made to tools outside the classification's selection.
+    #[serde(default)]
+    pub selection_misses: usize,
+    /// Whether the selection was dropped and every tool is offered again.
+    #[serde(default)]
+    pub tool_selection_widened: bool,
 }
 
 impl AgentState {
@@ -727,6 +1126,8 @@ impl AgentState {
             max_iterations,
             checkpoints: Vec::new(),
             task_classification: None,
+            selection_misses: 0,
+            tool_selection_widened: false,
         }
     }
 
@@ -738,6 +1139,18 @@ impl AgentState {
         self.current_goal = Some(goal_str);
         self.iteration = 0;
         self.checkpoints.clear();
+        self.selection_misses = 0;
+        self.tool_selection_widened = false;
+    }
+
+    /// The classification that selects the tools offered to the model, or
+    /// `None` when every tool is offered.
+    pub fn tool_selection(&self) -> Option<&TaskClassification> {
+        if self.tool_selection_widened {
+            None
+        } else {
+            self.task_classification.as_ref()
+        }
     }
 
     pub fn increment_iteration(&mut self) -> bool {
@@ -760,6 +1173,8 @@ impl AgentState {
         self.iteration = 0;
         self.checkpoints.clear();
         self.task_classification = None;
+        self.selection_misses = 0;
+        self.tool_selection_widened = false;
     }
 }
 
@@ -830,6 +1245,8 @@ pub struct CompletionResponse {
     pub usage: TokenUsage,
     pub model: String,
     pub finish_reason: Option<String>,
+    /// Set when the response was served from the response cache instead of the provider.
+    pub cache_hit: Option<crate::cache::CacheHit>,
 }
 
 /// A request to the LLM for completion.
@@ -2,8 +2,19 @@
 //!
 //! Checks for new versions via the GitHub Releases API and can download
 //! and replace the running binary.
+//!
+//! Installs are verified before anything on disk changes: the release
+//! asset's minisign signature (Ed25519, legacy `-l` mode) must match the
+//! public key embedded at build time via `RUSTANT_RELEASE_PUBKEY`, and the
+//! new binary must answer `--version` before it is swapped in. The previous
+//! binary is kept next to the current one as `.bak` for `rustant update
+//! rollback`.
 
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
 /// Current version of Rustant.
 pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -12,6 +23,12 @@ pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const GITHUB_OWNER: &str = "DevJadhav";
 const GITHUB_REPO: &str = "Rustant";
 
+/// Minisign public key for release signatures, embedded by the release build.
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("RUSTANT_RELEASE_PUBKEY");
+
+/// Directory (next to the binary) where downloads are staged.
+const STAGING_DIR: &str = ".rustant-update";
+
 /// Configuration for the update system.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateConfig {
@@ -21,7 +38,7 @@ pub struct UpdateConfig {
     /// Hours between update checks.
     #[serde(default = "default_check_interval")]
     pub check_interval_hours: u64,
-    /// Release channel: "stable" or "beta".
+    /// Release channel: "stable", "beta", or "nightly".
     #[serde(default = "default_channel")]
     pub channel: String,
 }
@@ -48,6 +65,62 @@ impl Default for UpdateConfig {
     }
 }
 
+impl UpdateConfig {
+    /// The configured release channel (unknown values fall back to stable).
+    pub fn release_channel(&self) -> ReleaseChannel {
+        ReleaseChannel::parse(&self.channel).unwrap_or(ReleaseChannel::Stable)
+    }
+}
+
+/// Which releases an installation follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseChannel {
+    /// Full releases only.
+    Stable,
+    /// Full releases and pre-releases.
+    Beta,
+    /// Everything, including nightly builds.
+    Nightly,
+}
+
+impl ReleaseChannel {
+    /// Parse a channel name (case-insensitive).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "stable" => Some(Self::Stable),
+            "beta" => Some(Self::Beta),
+            "nightly" => Some(Self::Nightly),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+            Self::Nightly => "nightly",
+        }
+    }
+
+    fn accepts(&self, release: &GitHubRelease) -> bool {
+        if release.draft {
+            return false;
+        }
+        let nightly = release.tag_name.to_lowercase().contains("nightly");
+        match self {
+            Self::Stable => !release.prerelease && !nightly,
+            Self::Beta => !nightly,
+            Self::Nightly => true,
+        }
+    }
+}
+
+impl fmt::Display for ReleaseChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Result of an update check.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateCheckResult {
@@ -74,19 +147,38 @@ impl UpdateChecker {
         Self { config }
     }
 
-    /// Check if an update is available.
+    /// Check if an update is available on the configured channel.
     pub async fn check(&self) -> Result<UpdateCheckResult, UpdateError> {
-        let client = reqwest::Client::builder()
-            .user_agent(format!("rustant/{}", CURRENT_VERSION))
-            .build()
-            .map_err(|e| UpdateError::NetworkError(e.to_string()))?;
+        let release = self.latest_release().await?;
+        Ok(match release {
+            Some(release) => {
+                let latest_version = release.version();
+                UpdateCheckResult {
+                    current_version: CURRENT_VERSION.into(),
+                    update_available: is_newer_version(&latest_version, CURRENT_VERSION),
+                    latest_version: Some(latest_version),
+                    release_url: Some(release.html_url),
+                    release_notes: Some(release.body.unwrap_or_default()),
+                }
+            }
+            None => UpdateCheckResult {
+                current_version: CURRENT_VERSION.into(),
+                latest_version: None,
+                update_available: false,
+                release_url: None,
+                release_notes: None,
+            },
+        })
+    }
 
+    /// Newest release on the configured channel.
+    async fn latest_release(&self) -> Result<Option<GitHubRelease>, UpdateError> {
         let url = format!(
-            "https://api.github.com/repos/{}/{}/releases/latest",
+            "https://api.github.com/repos/{}/{}/releases?per_page=30",
             GITHUB_OWNER, GITHUB_REPO
         );
 
-        let response = client
+        let response = http_client()?
             .get(&url)
             .send()
             .await
@@ -99,32 +191,12 @@ impl UpdateChecker {
             )));
         }
 
-        let release: GitHubRelease = response
+        let releases: Vec<GitHubRelease> = response
             .json()
             .await
             .map_err(|e| UpdateError::ParseError(e.to_string()))?;
 
-        let latest_version = release.tag_name.trim_start_matches('v').to_string();
-        let update_available = is_newer_version(&latest_version, CURRENT_VERSION);
-
-        // Filter by channel
-        if self.config.channel == "stable" && release.prerelease {
-            return Ok(UpdateCheckResult {
-                current_version: CURRENT_VERSION.into(),
-                latest_version: Some(latest_version),
-                update_available: false,
-                release_url: Some(release.html_url),
-                release_notes: Some(release.body.unwrap_or_default()),
-            });
-        }
-
-        Ok(UpdateCheckResult {
-            current_version: CURRENT_VERSION.into(),
-            latest_version: Some(latest_version),
-            update_available,
-            release_url: Some(release.html_url),
-            release_notes: Some(release.body.unwrap_or_default()),
-        })
+        Ok(select_release(&releases, self.config.release_channel()).cloned())
     }
 
     /// Get the update configuration.
@@ -133,32 +205,472 @@ impl UpdateChecker {
     }
 }
 
+fn http_client() -> Result<reqwest::Client, UpdateError> {
+    reqwest::Client::builder()
+        .user_agent(format!("rustant/{}", CURRENT_VERSION))
+        .build()
+        .map_err(|e| UpdateError::NetworkError(e.to_string()))
+}
+
+/// Pick the newest release a channel accepts.
+fn select_release(releases: &[GitHubRelease], channel: ReleaseChannel) -> Option<&GitHubRelease> {
+    releases
+        .iter()
+        .filter(|r| channel.accepts(r))
+        .max_by(|a, b| compare_versions(&a.version(), &b.version()))
+}
+
+/// Outcome of `Updater::install`.
+#[derive(Debug, Clone)]
+pub enum InstallOutcome {
+    /// Already on the newest release for the channel.
+    UpToDate { version: String },
+    /// A new binary was verified and swapped in.
+    Installed {
+        from: String,
+        to: String,
+        /// Where the previous binary was kept.
+        backup: PathBuf,
+    },
+}
+
 /// Performs the actual binary update.
 pub struct Updater;
 
 impl Updater {
-    /// Download and install the latest version.
-    pub fn update() -> Result<(), UpdateError> {
-        let status = self_update::backends::github::Update::configure()
-            .repo_owner(GITHUB_OWNER)
-            .repo_name(GITHUB_REPO)
-            .bin_name("rustant")
-            .current_version(CURRENT_VERSION)
-            .show_output(true)
-            .show_download_progress(true)
-            .build()
-            .map_err(|e| UpdateError::UpdateFailed(e.to_string()))?
-            .update()
-            .map_err(|e| UpdateError::UpdateFailed(e.to_string()))?;
+    /// Download, verify, self-check, and install the newest release on the
+    /// configured channel, keeping the current binary as `.bak`.
+    pub async fn install(config: &UpdateConfig) -> Result<InstallOutcome, UpdateError> {
+        let exe = current_exe()?;
+        check_install_location(&exe)?;
+        let public_key = RELEASE_PUBLIC_KEY.ok_or(UpdateError::NoSigningKey)?;
+
+        let checker = UpdateChecker::new(config.clone());
+        let Some(release) = checker.latest_release().await? else {
+            return Ok(InstallOutcome::UpToDate {
+                version: CURRENT_VERSION.into(),
+            });
+        };
+        let version = release.version();
+        if !is_newer_version(&version, CURRENT_VERSION) {
+            return Ok(InstallOutcome::UpToDate {
+                version: CURRENT_VERSION.into(),
+            });
+        }
+
+        let asset_name = platform_asset_name().ok_or_else(|| {
+            UpdateError::UpdateFailed("No prebuilt binary is published for this platform".into())
+        })?;
+        let asset = release.asset(asset_name).ok_or_else(|| {
+            UpdateError::UpdateFailed(format!(
+                "Release {} has no '{}' asset",
+                release.tag_name, asset_name
+            ))
+        })?;
+        let signature_name = format!("{}.minisig", asset_name);
+        let signature_asset = release.asset(&signature_name).ok_or_else(|| {
+            UpdateError::SignatureInvalid(format!(
+                "Release {} has no '{}'; refusing to install an unsigned binary",
+                release.tag_name, signature_name
+            ))
+        })?;
+
+        let install_dir = exe
+            .parent()
+            .ok_or_else(|| UpdateError::UpdateFailed("Binary has no parent directory".into()))?;
+        let staging = install_dir.join(STAGING_DIR);
+        std::fs::create_dir_all(&staging).map_err(|e| io_error(&staging, e))?;
+
+        let client = http_client()?;
+        let archive = staging.join(format!("{}-{}", version, asset_name));
+        download_resumable(&client, &asset.browser_download_url, &archive).await?;
+        let signature_path = staging.join(format!("{}-{}", version, signature_name));
+        download_resumable(
+            &client,
+            &signature_asset.browser_download_url,
+            &signature_path,
+        )
+        .await?;
+
+        let data = std::fs::read(&archive).map_err(|e| io_error(&archive, e))?;
+        let signature =
+            std::fs::read_to_string(&signature_path).map_err(|e| io_error(&signature_path, e))?;
+        if let Err(e) = verify_minisign(public_key, &signature, &data) {
+            // Never resume from a download that failed verification.
+            let _ = std::fs::remove_file(&archive);
+            let _ = std::fs::remove_file(&signature_path);
+            return Err(e);
+        }
+
+        let new_binary = install_dir.join(format!(".{}.new", binary_file_name(&exe)));
+        extract_binary(&archive, &new_binary)?;
+        if let Err(e) = self_check(&new_binary, &version) {
+            let _ = std::fs::remove_file(&new_binary);
+            return Err(e);
+        }
+
+        swap_in(&new_binary, &exe)?;
+        let _ = std::fs::remove_dir_all(&staging);
 
         tracing::info!(
             old_version = CURRENT_VERSION,
-            new_version = %status.version(),
+            new_version = %version,
             "Updated successfully"
         );
 
-        Ok(())
+        Ok(InstallOutcome::Installed {
+            from: CURRENT_VERSION.into(),
+            to: version,
+            backup: backup_path(&exe),
+        })
     }
+
+    /// Restore the `.bak` binary left by the last install. The replaced
+    /// binary becomes the new `.bak`, so a rollback can itself be undone.
+    /// Returns the restored binary's `--version` output.
+    pub fn rollback() -> Result<String, UpdateError> {
+        let exe = current_exe()?;
+        check_install_location(&exe)?;
+        rollback_binary(&exe)
+    }
+}
+
+fn current_exe() -> Result<PathBuf, UpdateError> {
+    let exe = std::env::current_exe()
+        .map_err(|e| UpdateError::UpdateFailed(format!("Cannot locate running binary: {}", e)))?;
+    Ok(exe.canonicalize().unwrap_or(exe))
+}
+
+fn binary_file_name(exe: &Path) -> String {
+    exe.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "rustant".into())
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> UpdateError {
+    UpdateError::UpdateFailed(format!("{}: {}", path.display(), e))
+}
+
+/// Release asset name for the running platform.
+pub fn platform_asset_name() -> Option<&'static str> {
+    if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        Some("rustant-linux-x86_64.tar.gz")
+    } else if cfg!(all(target_os = "linux", target_arch = "aarch64")) {
+        Some("rustant-linux-aarch64.tar.gz")
+    } else if cfg!(all(target_os = "macos", target_arch = "x86_64")) {
+        Some("rustant-macos-x86_64.tar.gz")
+    } else if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        Some("rustant-macos-aarch64.tar.gz")
+    } else if cfg!(all(target_os = "windows", target_arch = "x86_64")) {
+        Some("rustant-windows-x86_64.exe")
+    } else {
+        None
+    }
+}
+
+/// Refuse to touch binaries owned by a package manager, and explain how to
+/// proceed when the install directory is not writable.
+pub fn check_install_location(exe: &Path) -> Result<(), UpdateError> {
+    let path = exe.to_string_lossy();
+    let managed = if path.contains("/Cellar/") || path.contains("/homebrew/") {
+        Some("Homebrew; run `brew upgrade rustant` instead")
+    } else if path.starts_with("/nix/store/") {
+        Some("Nix; update it through your Nix profile or configuration instead")
+    } else if path.starts_with("/usr/bin/") || path.starts_with("/bin/") {
+        Some("the system package manager; update it with apt, dnf, or pacman instead")
+    } else {
+        None
+    };
+    if let Some(manager) = managed {
+        return Err(UpdateError::InstallLocation(format!(
+            "{} is managed by {}",
+            exe.display(),
+            manager
+        )));
+    }
+
+    let dir = exe
+        .parent()
+        .ok_or_else(|| UpdateError::UpdateFailed("Binary has no parent directory".into()))?;
+    let probe = dir.join(format!(".rustant-write-test-{}", std::process::id()));
+    match std::fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            let hint = if cfg!(windows) {
+                "Re-run `rustant update install` from an Administrator terminal".to_string()
+            } else {
+                format!(
+                    "Re-run with elevated privileges: `sudo {} update install`",
+                    exe.display()
+                )
+            };
+            Err(UpdateError::InstallLocation(format!(
+                "No write permission for {}. {}",
+                dir.display(),
+                hint
+            )))
+        }
+        Err(e) => Err(io_error(dir, e)),
+    }
+}
+
+/// Download `url` to `dest`, resuming a previous partial download
+/// (`dest.part`) with an HTTP range request when the server supports it and
+/// restarting from scratch when it does not.
+pub async fn download_resumable(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+) -> Result<(), UpdateError> {
+    use tokio::io::AsyncWriteExt;
+
+    let part = PathBuf::from(format!("{}.part", dest.display()));
+    for _ in 0..2 {
+        let offset = tokio::fs::metadata(&part)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let mut request = client.get(url);
+        if offset > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+        }
+        let mut response = request
+            .send()
+            .await
+            .map_err(|e| UpdateError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        let append = if status == reqwest::StatusCode::PARTIAL_CONTENT && offset > 0 {
+            true
+        } else if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            // Stale or oversized partial file: start over.
+            let _ = tokio::fs::remove_file(&part).await;
+            continue;
+        } else if status.is_success() {
+            false
+        } else {
+            return Err(UpdateError::NetworkError(format!(
+                "Download of {} returned status {}",
+                url, status
+            )));
+        };
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(&part)
+            .await
+            .map_err(|e| io_error(&part, e))?;
+        // A dropped connection leaves `.part` in place for the next attempt.
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| UpdateError::NetworkError(e.to_string()))?
+        {
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| io_error(&part, e))?;
+        }
+        file.flush().await.map_err(|e| io_error(&part, e))?;
+        drop(file);
+
+        tokio::fs::rename(&part, dest)
+            .await
+            .map_err(|e| io_error(dest, e))?;
+        return Ok(());
+    }
+    Err(UpdateError::NetworkError(format!(
+        "Server rejected resuming {}; restart the update",
+        url
+    )))
+}
+
+/// Verify a minisign signature (legacy Ed25519 mode, `minisign -S -l`)
+/// over `data`. The trusted comment and its global signature are required.
+pub fn verify_minisign(public_key: &str, signature: &str, data: &[u8]) -> Result<(), UpdateError> {
+    use ring::signature::{ED25519, UnparsedPublicKey};
+
+    let decode = |s: &str| {
+        base64::engine::general_purpose::STANDARD
+            .decode(s.trim())
+            .map_err(|e| UpdateError::SignatureInvalid(format!("invalid base64: {}", e)))
+    };
+
+    let key_line = public_key
+        .lines()
+        .map(str::trim)
+        .rfind(|l| !l.is_empty() && !l.starts_with("untrusted comment:"))
+        .ok_or_else(|| UpdateError::SignatureInvalid("empty public key".into()))?;
+    let key = decode(key_line)?;
+    if key.len() != 42 || &key[..2] != b"Ed" {
+        return Err(UpdateError::SignatureInvalid(
+            "public key is not a minisign Ed25519 key".into(),
+        ));
+    }
+
+    let mut lines = signature
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("untrusted comment:"));
+    let sig = decode(
+        lines
+            .next()
+            .ok_or_else(|| UpdateError::SignatureInvalid("empty signature file".into()))?,
+    )?;
+    if sig.len() != 74 {
+        return Err(UpdateError::SignatureInvalid(
+            "malformed minisign signature".into(),
+        ));
+    }
+    match &sig[..2] {
+        b"Ed" => {}
+        b"ED" => {
+            return Err(UpdateError::SignatureInvalid(
+                "prehashed minisign signatures are not supported; sign with `minisign -S -l`"
+                    .into(),
+            ));
+        }
+        _ => {
+            return Err(UpdateError::SignatureInvalid(
+                "unknown signature algorithm".into(),
+            ));
+        }
+    }
+    if sig[2..10] != key[2..10] {
+        return Err(UpdateError::SignatureInvalid(
+            "signed with a different key than the one embedded in this build".into(),
+        ));
+    }
+
+    let verifier = UnparsedPublicKey::new(&ED25519, &key[10..]);
+    verifier.verify(data, &sig[10..]).map_err(|_| {
+        UpdateError::SignatureInvalid("signature does not match the download".into())
+    })?;
+
+    // The global signature covers the signature and trusted comment, so a
+    // stripped or swapped comment is rejected like minisign does.
+    let comment = lines
+        .next()
+        .ok_or_else(|| UpdateError::SignatureInvalid("missing trusted comment".into()))?
+        .strip_prefix("trusted comment:")
+        .ok_or_else(|| UpdateError::SignatureInvalid("malformed trusted comment".into()))?
+        .trim_start();
+    let global = decode(lines.next().ok_or_else(|| {
+        UpdateError::SignatureInvalid("missing trusted comment signature".into())
+    })?)?;
+    let mut signed = sig[10..].to_vec();
+    signed.extend_from_slice(comment.as_bytes());
+    verifier.verify(&signed, &global).map_err(|_| {
+        UpdateError::SignatureInvalid("trusted comment signature does not match".into())
+    })?;
+    Ok(())
+}
+
+/// Extract the `rustant` binary from a release archive (or copy a bare
+/// binary) to `dest`, marking it executable.
+fn extract_binary(archive: &Path, dest: &Path) -> Result<(), UpdateError> {
+    let name = archive.to_string_lossy();
+    if name.ends_with(".tar.gz") {
+        let file = std::fs::File::open(archive).map_err(|e| io_error(archive, e))?;
+        let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        let entries = tar.entries().map_err(|e| io_error(archive, e))?;
+        let mut found = false;
+        for entry in entries {
+            let mut entry = entry.map_err(|e| io_error(archive, e))?;
+            let is_binary = entry
+                .path()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n == "rustant"))
+                .unwrap_or(false);
+            if is_binary {
+                entry.unpack(dest).map_err(|e| io_error(dest, e))?;
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            return Err(UpdateError::UpdateFailed(
+                "Release archive does not contain a rustant binary".into(),
+            ));
+        }
+    } else {
+        std::fs::copy(archive, dest).map_err(|e| io_error(dest, e))?;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dest, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| io_error(dest, e))?;
+    }
+    Ok(())
+}
+
+/// Run `binary --version` and require it to succeed (and to mention
+/// `expected_version` when one is given). Returns the reported version line.
+pub fn self_check(binary: &Path, expected_version: &str) -> Result<String, UpdateError> {
+    let output = std::process::Command::new(binary)
+        .arg("--version")
+        .output()
+        .map_err(|e| UpdateError::SelfCheckFailed(format!("{}: {}", binary.display(), e)))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() {
+        return Err(UpdateError::SelfCheckFailed(format!(
+            "{} --version exited with {}: {}",
+            binary.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    if !expected_version.is_empty() && !stdout.contains(expected_version) {
+        return Err(UpdateError::SelfCheckFailed(format!(
+            "expected version {}, binary reported '{}'",
+            expected_version, stdout
+        )));
+    }
+    Ok(stdout)
+}
+
+/// Backup location for `exe` (`rustant.bak`, `rustant.exe.bak`).
+pub fn backup_path(exe: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.bak", exe.display()))
+}
+
+/// Replace `target` with `new_binary`, keeping the old binary as `.bak`.
+/// Both renames stay within one directory, so each is atomic.
+fn swap_in(new_binary: &Path, target: &Path) -> Result<(), UpdateError> {
+    let backup = backup_path(target);
+    if backup.exists() {
+        std::fs::remove_file(&backup).map_err(|e| io_error(&backup, e))?;
+    }
+    std::fs::rename(target, &backup).map_err(|e| io_error(target, e))?;
+    if let Err(e) = std::fs::rename(new_binary, target) {
+        let _ = std::fs::rename(&backup, target);
+        return Err(io_error(target, e));
+    }
+    Ok(())
+}
+
+/// Swap `target` with its `.bak`, after checking the backup still runs.
+fn rollback_binary(target: &Path) -> Result<String, UpdateError> {
+    let backup = backup_path(target);
+    if !backup.exists() {
+        return Err(UpdateError::NoBackup(backup.display().to_string()));
+    }
+    let version = self_check(&backup, "")?;
+    let parked = PathBuf::from(format!("{}.rollback", target.display()));
+    std::fs::rename(target, &parked).map_err(|e| io_error(target, e))?;
+    if let Err(e) = std::fs::rename(&backup, target) {
+        let _ = std::fs::rename(&parked, target);
+        return Err(io_error(target, e));
+    }
+    std::fs::rename(&parked, &backup).map_err(|e| io_error(&backup, e))?;
+    Ok(version)
 }
 
 /// Errors from update operations.
@@ -170,33 +682,91 @@ pub enum UpdateError {
     ParseError(String),
     #[error("Update failed: {0}")]
     UpdateFailed(String),
+    #[error("Signature verification failed: {0}")]
+    SignatureInvalid(String),
+    #[error(
+        "This build has no release signing key, so downloads cannot be verified; install from https://github.com/DevJadhav/Rustant/releases"
+    )]
+    NoSigningKey,
+    #[error("New binary failed its self-check: {0}")]
+    SelfCheckFailed(String),
+    #[error("Cannot update in place: {0}")]
+    InstallLocation(String),
+    #[error("No previous binary to roll back to ({0} not found)")]
+    NoBackup(String),
 }
 
 /// GitHub release API response (subset).
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 struct GitHubRelease {
     tag_name: String,
     html_url: String,
     body: Option<String>,
     prerelease: bool,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    assets: Vec<GitHubAsset>,
+}
+
+impl GitHubRelease {
+    fn version(&self) -> String {
+        self.tag_name.trim_start_matches('v').to_string()
+    }
+
+    fn asset(&self, name: &str) -> Option<&GitHubAsset> {
+        self.assets.iter().find(|a| a.name == name)
+    }
+}
+
+/// A downloadable file attached to a release.
+#[derive(Debug, Clone, Deserialize)]
+struct GitHubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Split a version into its numeric core and optional pre-release label.
+fn parse_version(version: &str) -> ([u64; 3], Option<&str>) {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split('+').next().unwrap_or(version);
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    let mut parts = [0u64; 3];
+    for (slot, part) in parts.iter_mut().zip(core.split('.')) {
+        *slot = part.parse().unwrap_or(0);
+    }
+    (parts, pre)
+}
+
+/// Order versions semver-style: a pre-release sorts before its release.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_core, a_pre) = parse_version(a);
+    let (b_core, b_pre) = parse_version(b);
+    a_core.cmp(&b_core).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            for (x, y) in a.split('.').zip(b.split('.')) {
+                let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    _ => x.cmp(y),
+                };
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            a.split('.').count().cmp(&b.split('.').count())
+        }
+    })
 }
 
 /// Compare two semver versions. Returns true if `latest` is newer than `current`.
 pub fn is_newer_version(latest: &str, current: &str) -> bool {
-    let latest_parts: Vec<u32> = latest.split('.').filter_map(|p| p.parse().ok()).collect();
-    let current_parts: Vec<u32> = current.split('.').filter_map(|p| p.parse().ok()).collect();
-
-    for i in 0..3 {
-        let l = latest_parts.get(i).copied().unwrap_or(0);
-        let c = current_parts.get(i).copied().unwrap_or(0);
-        if l > c {
-            return true;
-        }
-        if l < c {
-            return false;
-        }
-    }
-    false // Equal
+    compare_versions(latest, current) == Ordering::Greater
 }
 
 #[cfg(test)]
@@ -273,4 +843,228 @@ mod tests {
         assert!(is_newer_version("0.0.1", "0.0.0"));
         assert!(is_newer_version("10.0.0", "9.9.9"));
     }
+
+    #[test]
+    fn test_prerelease_versions() {
+        assert!(is_newer_version("1.1.0-beta.1", "1.0.1"));
+        assert!(is_newer_version("1.1.0", "1.1.0-beta.2"));
+        assert!(is_newer_version("1.1.0-beta.10", "1.1.0-beta.2"));
+        assert!(!is_newer_version("1.1.0-beta.1", "1.1.0"));
+    }
+
+    fn release(tag: &str, prerelease: bool) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: tag.into(),
+            html_url: String::new(),
+            body: None,
+            prerelease,
+            draft: false,
+            assets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_select_release_by_channel() {
+        let releases = vec![
+            release("v1.2.0-nightly.20261014", true),
+            release("v1.2.0-beta.1", true),
+            release("v1.1.0", false),
+            release("v1.0.0", false),
+        ];
+        let pick = |c| select_release(&releases, c).map(|r| r.tag_name.clone());
+        assert_eq!(pick(ReleaseChannel::Stable).as_deref(), Some("v1.1.0"));
+        assert_eq!(pick(ReleaseChannel::Beta).as_deref(), Some("v1.2.0-beta.1"));
+        assert_eq!(
+            pick(ReleaseChannel::Nightly).as_deref(),
+            Some("v1.2.0-nightly.20261014")
+        );
+        assert_eq!(ReleaseChannel::parse("Beta"), Some(ReleaseChannel::Beta));
+        assert_eq!(
+            UpdateConfig {
+                channel: "weekly".into(),
+                ..UpdateConfig::default()
+            }
+            .release_channel(),
+            ReleaseChannel::Stable
+        );
+    }
+
+    /// Build a minisign public key and legacy-mode signature for `data`.
+    fn minisign_fixture(data: &[u8], comment: &str) -> (String, String) {
+        use ring::signature::{Ed25519KeyPair, KeyPair};
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let key_id = [7u8; 8];
+        let b64 = |b: &[u8]| base64::engine::general_purpose::STANDARD.encode(b);
+
+        let mut pk = b"Ed".to_vec();
+        pk.extend_from_slice(&key_id);
+        pk.extend_from_slice(pair.public_key().as_ref());
+
+        let sig = pair.sign(data);
+        let mut sig_blob = b"Ed".to_vec();
+        sig_blob.extend_from_slice(&key_id);
+        sig_blob.extend_from_slice(sig.as_ref());
+
+        let mut global_msg = sig.as_ref().to_vec();
+        global_msg.extend_from_slice(comment.as_bytes());
+        let global = pair.sign(&global_msg);
+
+        (
+            format!("untrusted comment: minisign public key\n{}\n", b64(&pk)),
+            format!(
+                "untrusted comment: signature\n{}\ntrusted comment: {}\n{}\n",
+                b64(&sig_blob),
+                comment,
+                b64(global.as_ref())
+            ),
+        )
+    }
+
+    #[test]
+    fn test_verify_minisign_accepts_valid_signature() {
+        let data = b"release archive bytes";
+        let (pk, sig) = minisign_fixture(data, "timestamp:1760000000\tfile:rustant.tar.gz");
+        verify_minisign(&pk, &sig, data).unwrap();
+    }
+
+    #[test]
+    fn test_verify_minisign_rejects_tampering() {
+        let data = b"release archive bytes";
+        let (pk, sig) = minisign_fixture(data, "file:rustant.tar.gz");
+        assert!(matches!(
+            verify_minisign(&pk, &sig, b"tampered archive bytes"),
+            Err(UpdateError::SignatureInvalid(_))
+        ));
+
+        let forged_comment = sig.replace("file:rustant.tar.gz", "file:other.tar.gz");
+        assert!(verify_minisign(&pk, &forged_comment, data).is_err());
+
+        let (other_pk, _) = minisign_fixture(data, "x");
+        assert!(verify_minisign(&other_pk, &sig, data).is_err());
+    }
+
+    #[test]
+    fn test_verify_minisign_requires_trusted_comment() {
+        let data = b"release archive bytes";
+        let (pk, sig) = minisign_fixture(data, "file:rustant.tar.gz");
+        let lines: Vec<&str> = sig.lines().collect();
+
+        // Signature line only: comment and global signature stripped.
+        let stripped = lines[..2].join("\n");
+        assert!(matches!(
+            verify_minisign(&pk, &stripped, data),
+            Err(UpdateError::SignatureInvalid(msg)) if msg.contains("missing trusted comment")
+        ));
+
+        // Comment kept but its global signature dropped.
+        let unsigned = lines[..3].join("\n");
+        assert!(matches!(
+            verify_minisign(&pk, &unsigned, data),
+            Err(UpdateError::SignatureInvalid(msg)) if msg == "missing trusted comment signature"
+        ));
+    }
+
+    #[cfg(unix)]
+    fn fake_binary(path: &Path, version: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::write(path, format!("#!/bin/sh\necho \"rustant {}\"\n", version)).unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_self_check_swap_and_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("rustant");
+        let new = dir.path().join(".rustant.new");
+        fake_binary(&exe, "1.0.0");
+        fake_binary(&new, "1.1.0");
+
+        assert!(self_check(&new, "9.9.9").is_err());
+        assert!(self_check(&new, "1.1.0").unwrap().contains("1.1.0"));
+
+        swap_in(&new, &exe).unwrap();
+        assert!(!new.exists());
+        assert!(self_check(&exe, "1.1.0").is_ok());
+        assert!(self_check(&backup_path(&exe), "1.0.0").is_ok());
+
+        let restored = rollback_binary(&exe).unwrap();
+        assert!(restored.contains("1.0.0"));
+        assert!(self_check(&exe, "1.0.0").is_ok());
+        // The replaced binary is kept, so the rollback can be undone.
+        assert!(self_check(&backup_path(&exe), "1.1.0").is_ok());
+    }
+
+    #[test]
+    fn test_rollback_without_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("rustant");
+        std::fs::write(&exe, b"bin").unwrap();
+        assert!(matches!(
+            rollback_binary(&exe),
+            Err(UpdateError::NoBackup(_))
+        ));
+    }
+
+    #[test]
+    fn test_managed_install_locations() {
+        let brew = Path::new("/opt/homebrew/Cellar/rustant/1.0.0/bin/rustant");
+        let err = check_install_location(brew).unwrap_err().to_string();
+        assert!(err.contains("brew upgrade"));
+        let nix = Path::new("/nix/store/abc-rustant/bin/rustant");
+        assert!(
+            check_install_location(nix)
+                .unwrap_err()
+                .to_string()
+                .contains("Nix")
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(check_install_location(&dir.path().join("rustant")).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_partial_file() {
+        use axum::{Router, http::HeaderMap, http::StatusCode, routing::get};
+
+        const BODY: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+        let app = Router::new().route(
+            "/asset",
+            get(|headers: HeaderMap| async move {
+                match headers
+                    .get("range")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.strip_prefix("bytes="))
+                    .and_then(|v| v.trim_end_matches('-').parse::<usize>().ok())
+                {
+                    Some(start) if start < BODY.len() => {
+                        (StatusCode::PARTIAL_CONTENT, BODY[start..].to_vec())
+                    }
+                    Some(_) => (StatusCode::RANGE_NOT_SATISFIABLE, Vec::new()),
+                    None => (StatusCode::OK, BODY.to_vec()),
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let url = format!("http://{}/asset", addr);
+        let client = reqwest::Client::new();
+        let dir = tempfile::tempdir().unwrap();
+
+        // Resume from a partial download.
+        let dest = dir.path().join("asset.tar.gz");
+        std::fs::write(dir.path().join("asset.tar.gz.part"), &BODY[..10]).unwrap();
+        download_resumable(&client, &url, &dest).await.unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), BODY);
+
+        // An oversized partial file is discarded and the download restarts.
+        let dest = dir.path().join("other.tar.gz");
+        std::fs::write(dir.path().join("other.tar.gz.part"), [0u8; 64]).unwrap();
+        download_resumable(&client, &url, &dest).await.unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), BODY);
+        assert!(!dir.path().join("other.tar.gz.part").exists());
+    }
 }
@@ -254,12 +254,18 @@ pub mod audio_convert {
 /// Encodes to a temporary WAV file and plays it using system commands:
 /// - macOS: `afplay`
 /// - Linux: `aplay`
+///
+/// Dropping the returned future stops playback, which is how a voice session
+/// cuts a reply short when the user talks over it.
 pub async fn play_audio(chunk: &AudioChunk) -> Result<(), VoiceError> {
     let wav_bytes = audio_convert::encode_wav(chunk)?;
-    let tmp_path = std::env::temp_dir().join("rustant_playback.wav");
+    let tmp_path =
+        std::env::temp_dir().join(format!("rustant_playback_{}.wav", uuid::Uuid::new_v4()));
     std::fs::write(&tmp_path, &wav_bytes).map_err(|e| VoiceError::AudioError {
         message: format!("Failed to write temp WAV: {}", e),
     })?;
+    // Clean up the temp file however playback ends.
+    let _cleanup = TempFile(tmp_path.clone());
 
     let cmd = if cfg!(target_os = "macos") {
         "afplay"
@@ -268,15 +274,13 @@ pub async fn play_audio(chunk: &AudioChunk) -> Result<(), VoiceError> {
     };
     let status = tokio::process::Command::new(cmd)
         .arg(&tmp_path)
+        .kill_on_drop(true)
         .status()
         .await
         .map_err(|e| VoiceError::AudioError {
             message: format!("{} failed: {}", cmd, e),
         })?;
 
-    // Clean up temp file
-    let _ = std::fs::remove_file(&tmp_path);
-
     if !status.success() {
         return Err(VoiceError::AudioError {
             message: format!("{} exited with status {}", cmd, status),
@@ -285,6 +289,15 @@ pub async fn play_audio(chunk: &AudioChunk) -> Result<(), VoiceError> {
     Ok(())
 }
 
+/// Removes a temporary file when dropped.
+struct TempFile(std::path::PathBuf);
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
 /// Record an audio chunk from the system microphone.
 ///
 /// On macOS, uses `afrecord` to capture audio for `duration_secs` seconds.
@@ -293,10 +306,11 @@ pub async fn record_audio_chunk(
     duration_secs: f32,
     sample_rate: u32,
 ) -> Result<AudioChunk, VoiceError> {
-    let tmp_path = std::env::temp_dir().join("rustant_mic_capture.wav");
-
-    // Remove stale file from a previous capture
-    let _ = std::fs::remove_file(&tmp_path);
+    // Unique per capture: the meeting silence monitor and live transcription
+    // record concurrently.
+    let tmp_path =
+        std::env::temp_dir().join(format!("rustant_mic_capture_{}.wav", uuid::Uuid::new_v4()));
+    let _cleanup = TempFile(tmp_path.clone());
 
     #[cfg(target_os = "macos")]
     {
@@ -312,6 +326,7 @@ pub async fn record_audio_chunk(
                 &sample_rate.to_string(),
                 tmp_path.to_str().unwrap(),
             ])
+            .kill_on_drop(true)
             .status()
             .await
             .map_err(|e| VoiceError::AudioError {
@@ -339,6 +354,7 @@ pub async fn record_audio_chunk(
                 &sample_rate.to_string(),
                 tmp_path.to_str().unwrap(),
             ])
+            .kill_on_drop(true)
             .status()
             .await
             .map_err(|e| VoiceError::AudioError {
@@ -355,7 +371,6 @@ pub async fn record_audio_chunk(
     let data = std::fs::read(&tmp_path).map_err(|e| VoiceError::AudioError {
         message: format!("Failed to read recorded audio: {}", e),
     })?;
-    let _ = std::fs::remove_file(&tmp_path);
 
     audio_convert::decode_wav(&data)
 }
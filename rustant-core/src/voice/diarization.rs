@@ -0,0 +1,272 @@
+//! Speaker diarization — lightweight spectral clustering, pure computation.
+//!
+//! Each utterance is reduced to a voice fingerprint: the share of energy in
+//! a handful of frequency bands (measured with the Goertzel algorithm) plus
+//! the zero-crossing rate, computed over voiced frames only. Fingerprints are
+//! clustered online by cosine similarity, so labels ("Speaker 1", "Speaker 2",
+//! …) are assigned in order of first appearance and stay stable for the
+//! lifetime of the [`SpeakerDiarizer`]. This separates voices with distinct
+//! pitch and timbre well enough to attribute meeting notes; it is not a
+//! speaker-verification model.
+
+use super::audio_io::audio_convert;
+use super::types::{AudioChunk, TranscriptionSegment};
+use crate::search::cosine_similarity;
+
+/// Centre frequencies (Hz) of the fingerprint bands.
+const BANDS_HZ: [f32; 8] = [150.0, 250.0, 400.0, 600.0, 900.0, 1300.0, 2000.0, 3000.0];
+/// Analysis frame length in seconds.
+const FRAME_SECS: f32 = 0.025;
+/// Frames quieter than this fraction of the loudest frame are ignored.
+const VOICED_RATIO: f32 = 0.1;
+/// Absolute RMS below which a frame is treated as silence.
+const SILENCE_RMS: f32 = 0.002;
+
+/// Default minimum similarity for an utterance to join an existing speaker.
+pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.9;
+
+struct SpeakerCluster {
+    label: String,
+    centroid: Vec<f32>,
+    utterances: usize,
+}
+
+/// Assigns speaker labels to utterances by clustering voice fingerprints.
+pub struct SpeakerDiarizer {
+    threshold: f32,
+    max_speakers: usize,
+    speakers: Vec<SpeakerCluster>,
+}
+
+impl SpeakerDiarizer {
+    /// Create a diarizer that distinguishes at most `max_speakers` voices.
+    pub fn new(max_speakers: usize) -> Self {
+        Self {
+            threshold: DEFAULT_SIMILARITY_THRESHOLD,
+            max_speakers: max_speakers.max(1),
+            speakers: Vec::new(),
+        }
+    }
+
+    /// Set the minimum cosine similarity for joining an existing speaker.
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Number of distinct speakers seen so far.
+    pub fn speaker_count(&self) -> usize {
+        self.speakers.len()
+    }
+
+    /// Label one utterance. Returns `None` when it contains no voiced audio.
+    pub fn identify(&mut self, audio: &AudioChunk) -> Option<String> {
+        let features = voice_fingerprint(audio)?;
+        let best = self
+            .speakers
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i, cosine_similarity(&features, &s.centroid)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        let index = match best {
+            Some((i, sim)) if sim >= self.threshold || self.speakers.len() >= self.max_speakers => {
+                i
+            }
+            _ => {
+                self.speakers.push(SpeakerCluster {
+                    label: format!("Speaker {}", self.speakers.len() + 1),
+                    centroid: vec![0.0; features.len()],
+                    utterances: 0,
+                });
+                self.speakers.len() - 1
+            }
+        };
+
+        let speaker = &mut self.speakers[index];
+        speaker.utterances += 1;
+        let weight = 1.0 / speaker.utterances as f32;
+        for (c, f) in speaker.centroid.iter_mut().zip(&features) {
+            *c += (f - *c) * weight;
+        }
+        Some(speaker.label.clone())
+    }
+
+    /// Label `segments` using the matching slices of `audio`.
+    ///
+    /// `audio_start_secs` is the segment timeline position of the first
+    /// sample in `audio`. Segments with no voiced audio keep their label.
+    pub fn label_segments(
+        &mut self,
+        audio: &AudioChunk,
+        audio_start_secs: f32,
+        segments: &mut [TranscriptionSegment],
+    ) {
+        let frame_rate = audio.sample_rate as f32;
+        let frames = audio.num_frames();
+        for segment in segments {
+            let start = ((segment.start_secs - audio_start_secs).max(0.0) * frame_rate) as usize;
+            let end = (((segment.end_secs - audio_start_secs).max(0.0) * frame_rate) as usize)
+                .min(frames);
+            if start >= end {
+                continue;
+            }
+            let channels = audio.channels.max(1) as usize;
+            let slice = AudioChunk::new(
+                audio.samples[start * channels..end * channels].to_vec(),
+                audio.sample_rate,
+                audio.channels,
+            );
+            if let Some(label) = self.identify(&slice) {
+                segment.speaker = Some(label);
+            }
+        }
+    }
+}
+
+/// Compute the L2-normalised voice fingerprint of an utterance.
+///
+/// Returns `None` for silence or audio too short to analyse.
+pub fn voice_fingerprint(audio: &AudioChunk) -> Option<Vec<f32>> {
+    let samples = if audio.channels == 2 {
+        audio_convert::stereo_to_mono(&audio.samples)
+    } else {
+        audio.samples.clone()
+    };
+    let frame_len = (audio.sample_rate as f32 * FRAME_SECS) as usize;
+    if frame_len == 0 || samples.len() < frame_len {
+        return None;
+    }
+
+    let frames: Vec<&[f32]> = samples.chunks_exact(frame_len).collect();
+    let rms: Vec<f32> = frames
+        .iter()
+        .map(|f| (f.iter().map(|s| s * s).sum::<f32>() / f.len() as f32).sqrt())
+        .collect();
+    let loudest = rms.iter().copied().fold(0.0f32, f32::max);
+    if loudest < SILENCE_RMS {
+        return None;
+    }
+
+    let mut bands = [0.0f32; BANDS_HZ.len()];
+    let mut zero_crossings = 0.0f32;
+    let mut voiced = 0usize;
+    for (frame, energy) in frames.iter().zip(&rms) {
+        if *energy < loudest * VOICED_RATIO {
+            continue;
+        }
+        voiced += 1;
+        let powers: Vec<f32> = BANDS_HZ
+            .iter()
+            .map(|hz| goertzel_power(frame, *hz, audio.sample_rate as f32))
+            .collect();
+        let total: f32 = powers.iter().sum();
+        if total > 0.0 {
+            for (band, power) in bands.iter_mut().zip(&powers) {
+                *band += power / total;
+            }
+        }
+        let crossings = frame
+            .windows(2)
+            .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+            .count();
+        zero_crossings += crossings as f32 / frame.len() as f32;
+    }
+
+    let mut features: Vec<f32> = bands.iter().map(|b| b / voiced as f32).collect();
+    features.push(zero_crossings / voiced as f32);
+    let norm = features.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return None;
+    }
+    features.iter_mut().for_each(|x| *x /= norm);
+    Some(features)
+}
+
+/// Signal power at `frequency` within `frame` (Goertzel algorithm).
+fn goertzel_power(frame: &[f32], frequency: f32, sample_rate: f32) -> f32 {
+    let coeff = 2.0 * (2.0 * std::f32::consts::PI * frequency / sample_rate).cos();
+    let (mut s1, mut s2) = (0.0f32, 0.0f32);
+    for &x in frame {
+        let s0 = x + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    (s1 * s1 + s2 * s2 - coeff * s1 * s2).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(hz: f32, amplitude: f32, secs: f32) -> AudioChunk {
+        let rate = 16000;
+        let samples = (0..(rate as f32 * secs) as usize)
+            .map(|i| {
+                let t = i as f32 / rate as f32;
+                amplitude * (2.0 * std::f32::consts::PI * hz * t).sin()
+            })
+            .collect();
+        AudioChunk::new(samples, rate, 1)
+    }
+
+    #[test]
+    fn test_silence_has_no_fingerprint() {
+        assert!(voice_fingerprint(&AudioChunk::silence(16000, 1, 16000)).is_none());
+        assert!(voice_fingerprint(&AudioChunk::new(vec![0.5; 10], 16000, 1)).is_none());
+    }
+
+    #[test]
+    fn test_distinct_voices_get_distinct_labels() {
+        let mut diarizer = SpeakerDiarizer::new(4);
+        assert_eq!(
+            diarizer.identify(&tone(150.0, 0.3, 1.0)).as_deref(),
+            Some("Speaker 1")
+        );
+        assert_eq!(
+            diarizer.identify(&tone(1300.0, 0.3, 1.0)).as_deref(),
+            Some("Speaker 2")
+        );
+        // Louder or quieter, the same voice keeps its label.
+        assert_eq!(
+            diarizer.identify(&tone(150.0, 0.8, 0.5)).as_deref(),
+            Some("Speaker 1")
+        );
+        assert_eq!(diarizer.speaker_count(), 2);
+    }
+
+    #[test]
+    fn test_max_speakers_caps_new_labels() {
+        let mut diarizer = SpeakerDiarizer::new(1);
+        diarizer.identify(&tone(150.0, 0.3, 1.0));
+        assert_eq!(
+            diarizer.identify(&tone(2000.0, 0.3, 1.0)).as_deref(),
+            Some("Speaker 1")
+        );
+        assert_eq!(diarizer.speaker_count(), 1);
+    }
+
+    #[test]
+    fn test_label_segments_uses_segment_audio() {
+        let mut audio = tone(150.0, 0.3, 1.0);
+        audio.append(&tone(1300.0, 0.3, 1.0));
+        let segment = |start: f32, end: f32| TranscriptionSegment {
+            text: String::new(),
+            start_secs: start,
+            end_secs: end,
+            confidence: 1.0,
+            speaker: None,
+        };
+        // Audio begins 10s into the meeting.
+        let mut segments = vec![
+            segment(10.0, 11.0),
+            segment(11.0, 12.0),
+            segment(13.0, 14.0),
+        ];
+        let mut diarizer = SpeakerDiarizer::new(4);
+        diarizer.label_segments(&audio, 10.0, &mut segments);
+        assert_eq!(segments[0].speaker.as_deref(), Some("Speaker 1"));
+        assert_eq!(segments[1].speaker.as_deref(), Some("Speaker 2"));
+        assert_eq!(segments[2].speaker, None);
+    }
+}
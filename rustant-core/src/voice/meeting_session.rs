@@ -6,7 +6,20 @@
 //!
 //! Uses core's own voice APIs and direct `afrecord` process management
 //! to avoid circular dependency with `rustant-tools`.
+//!
+//! With `live_transcription` enabled, microphone audio is also captured in
+//! short chunks and fed to a [`StreamingTranscriber`], so segments appear in
+//! [`MeetingStatus`] during the meeting. Otherwise the recording is
+//! transcribed after it stops. Either way, `diarization` labels segments with
+//! speakers and `stt_provider = "whisper-local"` keeps audio on the machine.
 
+#[cfg(target_os = "macos")]
+use super::diarization::SpeakerDiarizer;
+#[cfg(target_os = "macos")]
+use super::streaming::StreamingTranscriber;
+use super::streaming::{LiveTranscript, TranscriptionMode};
+use super::stt::{OpenAiSttProvider, SttProvider};
+use super::types::TranscriptionSegment;
 use crate::config::MeetingConfig;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -15,6 +28,10 @@ use tokio::task::JoinHandle;
 #[allow(unused_imports)]
 use tracing::{debug, info, warn};
 
+/// Length of each live capture chunk in seconds.
+#[cfg(target_os = "macos")]
+const LIVE_CAPTURE_SECS: f32 = 2.0;
+
 /// Result returned when a meeting recording is stopped.
 #[derive(Debug, Clone)]
 pub struct MeetingResult {
@@ -28,6 +45,23 @@ pub struct MeetingResult {
     pub duration_secs: u64,
     /// Path to the recorded audio file.
     pub audio_path: String,
+    /// Timestamped transcript segments, with speakers when diarization is on.
+    pub segments: Vec<TranscriptionSegment>,
+    /// Whether transcription ran live, after the meeting, or fell back from live.
+    pub transcription_mode: TranscriptionMode,
+}
+
+impl MeetingResult {
+    /// Distinct speaker labels in order of first appearance.
+    pub fn speakers(&self) -> Vec<&str> {
+        let mut speakers: Vec<&str> = Vec::new();
+        for speaker in self.segments.iter().filter_map(|s| s.speaker.as_deref()) {
+            if !speakers.contains(&speaker) {
+                speakers.push(speaker);
+            }
+        }
+        speakers
+    }
 }
 
 /// Snapshot of the current recording state.
@@ -43,6 +77,8 @@ pub struct MeetingStatus {
     pub audio_path: Option<String>,
     /// How long the recording has been running (seconds).
     pub elapsed_secs: u64,
+    /// Segments transcribed so far (`None` when transcribing after the meeting).
+    pub live_transcript: Option<LiveTranscript>,
 }
 
 /// A meeting recording session with background silence monitoring
@@ -54,6 +90,9 @@ pub struct MeetingRecordingSession {
     monitor_handle: Option<JoinHandle<()>>,
     active: Arc<AtomicBool>,
     state: Arc<Mutex<SessionState>>,
+    live_transcript: Option<Arc<std::sync::Mutex<LiveTranscript>>>,
+    #[cfg(target_os = "macos")]
+    live: Option<LiveSession>,
 }
 
 #[derive(Debug, Clone)]
@@ -63,7 +102,6 @@ struct SessionState {
     audio_path: String,
     title: String,
     started_at: chrono::DateTime<chrono::Utc>,
-    #[allow(dead_code)]
     config: MeetingConfig,
 }
 
@@ -112,6 +150,18 @@ impl MeetingRecordingSession {
         let (cancel_tx, cancel_rx) = watch::channel(false);
         let active = Arc::new(AtomicBool::new(true));
 
+        let live = if config.live_transcription {
+            match meeting_stt_provider(&config) {
+                Ok(provider) => Some(LiveSession::spawn(provider, &config, cancel_rx.clone())),
+                Err(e) => {
+                    warn!(error = %e, "Live transcription unavailable; transcribing after the meeting");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         let state = Arc::new(Mutex::new(SessionState {
             pid,
             audio_path: audio_path.clone(),
@@ -138,6 +188,8 @@ impl MeetingRecordingSession {
             monitor_handle,
             active,
             state,
+            live_transcript: live.as_ref().map(|l| Arc::clone(&l.transcript)),
+            live,
         })
     }
 
@@ -174,22 +226,41 @@ impl MeetingRecordingSession {
             .signed_duration_since(state.started_at)
             .num_seconds() as u64;
 
-        // Transcribe using core's own OpenAiSttProvider.
-        let transcript = match std::env::var("OPENAI_API_KEY") {
-            Ok(api_key) if std::path::Path::new(&state.audio_path).exists() => {
-                match transcribe_file(&state.audio_path, &api_key).await {
-                    Ok(text) => text,
+        let live = match self.live {
+            Some(live) => live.finish().await,
+            None => None,
+        };
+        let (mut segments, transcription_mode, mut transcript) = match live {
+            Some(live) => (live.segments, live.mode, String::new()),
+            None if std::path::Path::new(&state.audio_path).exists() => {
+                match transcribe_recording(&state.config, &state.audio_path).await {
+                    Ok((text, segments)) => (segments, TranscriptionMode::Batch, text),
                     Err(e) => {
                         warn!(error = %e, "Transcription failed");
-                        String::new()
+                        (Vec::new(), TranscriptionMode::Batch, String::new())
                     }
                 }
             }
-            _ => {
-                warn!("OPENAI_API_KEY not set or audio file missing — skipping transcription");
-                String::new()
+            None => {
+                warn!("Audio file missing — skipping transcription");
+                (Vec::new(), TranscriptionMode::Batch, String::new())
             }
         };
+        segments.retain(|s| !s.text.trim().is_empty());
+        if !segments.is_empty() {
+            transcript = if segments.iter().any(|s| s.speaker.is_some()) {
+                super::streaming::attributed_transcript(&segments)
+            } else {
+                segments
+                    .iter()
+                    .map(|s| s.text.trim())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+        }
+        if let TranscriptionMode::FellBackToBatch { .. } = &transcription_mode {
+            info!(mode = %transcription_mode, "Meeting transcription fell back to batch");
+        }
 
         Ok(MeetingResult {
             transcript,
@@ -197,6 +268,8 @@ impl MeetingRecordingSession {
             notes_saved: false,
             duration_secs: elapsed,
             audio_path: state.audio_path.clone(),
+            segments,
+            transcription_mode,
         })
     }
 
@@ -224,38 +297,146 @@ impl MeetingRecordingSession {
             title: Some(state.title.clone()),
             audio_path: Some(state.audio_path.clone()),
             elapsed_secs: elapsed,
+            live_transcript: self
+                .live_transcript
+                .as_ref()
+                .map(|t| t.lock().unwrap().clone()),
+        }
+    }
+}
+
+/// Live capture feeding a streaming transcriber.
+#[cfg(target_os = "macos")]
+struct LiveSession {
+    transcript: Arc<std::sync::Mutex<LiveTranscript>>,
+    capture: JoinHandle<()>,
+    worker: JoinHandle<StreamingTranscriber>,
+}
+
+#[cfg(target_os = "macos")]
+impl LiveSession {
+    /// Start capturing microphone chunks until `cancel_rx` fires.
+    ///
+    /// Capture and transcription run as separate tasks so slow windows
+    /// queue audio instead of dropping it.
+    fn spawn(
+        provider: Arc<dyn SttProvider>,
+        config: &MeetingConfig,
+        cancel_rx: watch::Receiver<bool>,
+    ) -> Self {
+        let mut transcriber = StreamingTranscriber::new(provider);
+        if config.diarization {
+            transcriber = transcriber.with_diarizer(SpeakerDiarizer::new(config.max_speakers));
+        }
+        let transcript = transcriber.transcript();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let sample_rate = config.sample_rate;
+        let capture = tokio::spawn(async move {
+            // Finish the chunk in flight before stopping so no audio is lost.
+            while !*cancel_rx.borrow() {
+                match crate::voice::audio_io::record_audio_chunk(LIVE_CAPTURE_SECS, sample_rate)
+                    .await
+                {
+                    Ok(chunk) => {
+                        if tx.send(chunk).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => {
+                        warn!(error = %e, "Live capture failed; transcribing after the meeting");
+                        return;
+                    }
+                }
+            }
+        });
+        let worker = tokio::spawn(async move {
+            while let Some(chunk) = rx.recv().await {
+                transcriber.push(&chunk).await;
+            }
+            transcriber
+        });
+
+        info!("Live meeting transcription started");
+        Self {
+            transcript,
+            capture,
+            worker,
+        }
+    }
+
+    /// Stop capturing and return the transcript, or `None` when nothing was
+    /// transcribed live and the recording should be transcribed instead.
+    async fn finish(self) -> Option<LiveTranscript> {
+        let timeout = std::time::Duration::from_secs_f32(LIVE_CAPTURE_SECS + 5.0);
+        let _ = tokio::time::timeout(timeout, self.capture).await;
+        let transcriber = self.worker.await.ok()?;
+        if transcriber.received_secs() == 0.0 {
+            return None;
+        }
+        match transcriber.finish().await {
+            Ok(transcript) => Some(transcript),
+            Err(e) => {
+                warn!(error = %e, "Final live transcription pass failed; transcribing the recording");
+                None
+            }
         }
     }
 }
 
-/// Transcribe a WAV file using core's own `OpenAiSttProvider`.
+/// Build the transcription provider selected by `config.stt_provider`.
+///
+/// `"whisper-local"` runs on-device and requires the `voice` feature and a
+/// downloaded model; anything else uses the OpenAI API with `OPENAI_API_KEY`.
+pub fn meeting_stt_provider(config: &MeetingConfig) -> Result<Arc<dyn SttProvider>, String> {
+    if config.stt_provider == "whisper-local" {
+        #[cfg(feature = "voice")]
+        {
+            let provider =
+                super::stt::WhisperLocalProvider::from_config(&config.whisper, &config.language);
+            if !provider.model_available() {
+                return Err(format!(
+                    "Whisper model not found at {}. Download ggml-{}.bin or set [meeting.whisper] model_path.",
+                    provider.model_path, config.whisper.model
+                ));
+            }
+            return Ok(Arc::new(provider));
+        }
+        #[cfg(not(feature = "voice"))]
+        return Err("Local Whisper transcription requires a build with the `voice` feature".into());
+    }
+    let api_key = std::env::var("OPENAI_API_KEY")
+        .map_err(|_| "OPENAI_API_KEY not set — cannot transcribe".to_string())?;
+    Ok(Arc::new(
+        OpenAiSttProvider::new(api_key).with_language(&config.language),
+    ))
+}
+
+/// Transcribe a recorded WAV file with the configured provider.
+///
+/// Returns the full text and the timestamped (and, with diarization, speaker
+/// labelled) segments.
 #[cfg(target_os = "macos")]
-async fn transcribe_file(audio_path: &str, api_key: &str) -> Result<String, String> {
+async fn transcribe_recording(
+    config: &MeetingConfig,
+    audio_path: &str,
+) -> Result<(String, Vec<TranscriptionSegment>), String> {
     use crate::voice::audio_io::audio_convert;
-    use crate::voice::stt::{OpenAiSttProvider, SttProvider};
     use crate::voice::types::AudioChunk;
 
+    let provider = meeting_stt_provider(config)?;
+
     let wav_data =
         std::fs::read(audio_path).map_err(|e| format!("Failed to read audio file: {e}"))?;
 
     let chunk =
         audio_convert::decode_wav(&wav_data).map_err(|e| format!("Failed to decode WAV: {e}"))?;
 
-    let provider = OpenAiSttProvider::new(api_key);
-
     // Chunk limit: 10 minutes at 16kHz mono.
     const CHUNK_SAMPLES: usize = 16000 * 600;
 
-    if chunk.samples.len() <= CHUNK_SAMPLES {
-        let result = provider
-            .transcribe(&chunk)
-            .await
-            .map_err(|e| format!("Transcription failed: {e}"))?;
-        return Ok(result.text);
-    }
-
-    // Multi-chunk transcription for long recordings.
     let mut full_transcript = String::new();
+    let mut segments = Vec::new();
     let mut offset = 0;
 
     while offset < chunk.samples.len() {
@@ -265,6 +446,7 @@ async fn transcribe_file(audio_path: &str, api_key: &str) -> Result<String, Stri
             chunk.sample_rate,
             chunk.channels,
         );
+        let offset_secs = offset as f32 / (chunk.sample_rate as f32 * chunk.channels.max(1) as f32);
 
         let result = provider
             .transcribe(&sub_chunk)
@@ -275,10 +457,15 @@ async fn transcribe_file(audio_path: &str, api_key: &str) -> Result<String, Stri
             full_transcript.push(' ');
         }
         full_transcript.push_str(&result.text);
+        segments.extend(result.segments.into_iter().map(|s| s.offset(offset_secs)));
         offset = end;
     }
 
-    Ok(full_transcript)
+    if config.diarization {
+        SpeakerDiarizer::new(config.max_speakers).label_segments(&chunk, 0.0, &mut segments);
+    }
+
+    Ok((full_transcript, segments))
 }
 
 /// Background silence monitor that auto-stops recording after sustained silence.
@@ -369,6 +556,8 @@ mod tests {
             notes_saved: false,
             duration_secs: 60,
             audio_path: "/tmp/test.wav".into(),
+            segments: Vec::new(),
+            transcription_mode: TranscriptionMode::Batch,
         };
         assert_eq!(result.duration_secs, 60);
         assert!(!result.notes_saved);
@@ -383,8 +572,48 @@ mod tests {
             title: None,
             audio_path: None,
             elapsed_secs: 0,
+            live_transcript: None,
         };
         assert!(!status.is_recording);
         assert_eq!(status.elapsed_secs, 0);
     }
+
+    #[test]
+    fn test_meeting_result_speakers_in_order() {
+        let segment = |speaker: Option<&str>| TranscriptionSegment {
+            text: "hi".into(),
+            start_secs: 0.0,
+            end_secs: 1.0,
+            confidence: 1.0,
+            speaker: speaker.map(String::from),
+        };
+        let result = MeetingResult {
+            transcript: String::new(),
+            summary: String::new(),
+            notes_saved: false,
+            duration_secs: 3,
+            audio_path: String::new(),
+            segments: vec![
+                segment(Some("Speaker 2")),
+                segment(None),
+                segment(Some("Speaker 1")),
+                segment(Some("Speaker 2")),
+            ],
+            transcription_mode: TranscriptionMode::Live,
+        };
+        assert_eq!(result.speakers(), vec!["Speaker 2", "Speaker 1"]);
+    }
+
+    #[test]
+    fn test_whisper_local_provider_requires_model() {
+        let config = MeetingConfig {
+            stt_provider: "whisper-local".into(),
+            whisper: crate::voice::WhisperConfig {
+                model_path: Some("/nonexistent/ggml-tiny.bin".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(meeting_stt_provider(&config).is_err());
+    }
 }
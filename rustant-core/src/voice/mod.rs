@@ -10,8 +10,10 @@
 //! detection require the `voice` feature flag.
 
 pub mod audio_io;
+pub mod diarization;
 pub mod meeting_session;
 pub mod session;
+pub mod streaming;
 pub mod stt;
 pub mod toggle;
 pub mod tts;
@@ -24,7 +26,9 @@ pub mod pipeline;
 
 // Re-export core types (always available)
 pub use audio_io::{audio_convert, play_audio, record_audio_chunk};
-pub use stt::{MockSttProvider, OpenAiSttProvider, SttProvider};
+pub use diarization::SpeakerDiarizer;
+pub use streaming::{LiveTranscript, StreamingTranscriber, TranscriptionMode};
+pub use stt::{ComputeDevice, MockSttProvider, OpenAiSttProvider, SttProvider, WhisperConfig};
 pub use tts::{MockTtsProvider, OpenAiTtsProvider, TtsProvider};
 pub use types::{
     AudioChunk, AudioFormat, SynthesisRequest, SynthesisResult, TranscriptionResult,
@@ -35,8 +39,8 @@ pub use wake::{MockWakeDetector, SttWakeDetector, WakeWordDetector};
 
 // Voice & meeting session toggles
 pub use meeting_session::{MeetingRecordingSession, MeetingResult, MeetingStatus};
-pub use session::VoiceCommandSession;
-pub use toggle::ToggleState;
+pub use session::{VoiceCommandSession, VoiceConfirmation, VoiceControl, VoiceTurn};
+pub use toggle::{ToggleState, VoiceDenial};
 
 // Feature-gated re-exports
 #[cfg(feature = "voice")]
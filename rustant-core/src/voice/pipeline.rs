@@ -104,14 +104,12 @@ impl VoicePipeline {
         }
 
         // Wake word detected! Record the full command
-        let command_chunk =
-            super::audio_io::record_audio_chunk(self.config.max_listen_secs as f32, 16000).await?;
-
-        // Transcribe
-        let transcription = self.stt.transcribe(&command_chunk).await?;
+        let Some(text) = self.record_command().await? else {
+            return Ok(None);
+        };
 
         // Strip the wake word from the transcription
-        let text = transcription.text.to_lowercase();
+        let text = text.to_lowercase();
         let command = self
             .config
             .wake_words
@@ -126,6 +124,21 @@ impl VoicePipeline {
 
         Ok(Some(command))
     }
+
+    /// Record a command for up to `max_listen_secs` and transcribe it,
+    /// without waiting for a wake word (push-to-talk).
+    ///
+    /// Returns `Ok(None)` if nothing was said.
+    pub async fn record_command(&self) -> Result<Option<String>, VoiceError> {
+        let chunk =
+            super::audio_io::record_audio_chunk(self.config.max_listen_secs as f32, 16000).await?;
+        if !self.vad.is_speech(&chunk) {
+            return Ok(None);
+        }
+        let transcription = self.stt.transcribe(&chunk).await?;
+        let text = transcription.text.trim();
+        Ok((!text.is_empty()).then(|| text.to_string()))
+    }
 }
 
 #[cfg(test)]
@@ -1,59 +1,153 @@
 //! Voice command session — background listen→transcribe→respond loop.
 //!
 //! Runs as a `tokio::spawn` task with graceful shutdown via `watch` channel.
+//! Each utterance is handed to the host as a [`VoiceTurn`] so it runs through
+//! the same agent conversation as typed input, and the reply is spoken back.
+//! After a reply the session listens for `follow_up_secs` without the wake
+//! word, for up to `max_turns` turns. Talking over a reply stops playback and
+//! becomes the next turn.
 
-use crate::config::AgentConfig;
+use crate::config::{AgentConfig, VoiceConfig};
 use crate::error::VoiceError;
+use crate::voice::audio_io::{play_audio, record_audio_chunk};
+use crate::voice::stt::{OpenAiSttProvider, SttProvider};
+use crate::voice::tts::{MockTtsProvider, OpenAiTtsProvider, TtsProvider};
+use crate::voice::types::{AudioChunk, SynthesisRequest};
+use crate::voice::vad::{VadEvent, VoiceActivityDetector};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use tokio::sync::watch;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
+/// Sample rate for microphone capture.
+const SAMPLE_RATE: u32 = 16000;
+
+/// Recording granularity. Short chunks keep barge-in and end-of-speech
+/// detection responsive.
+const CHUNK_SECS: f32 = 1.0;
+
+/// The microphone also hears the reply being played, so interrupting it
+/// takes louder speech than the listening threshold.
+const BARGE_IN_THRESHOLD_FACTOR: f32 = 3.0;
+
+/// Shortest window for the command after the wake word is said on its own.
+const MIN_COMMAND_WINDOW: Duration = Duration::from_secs(5);
+
+/// An utterance for the host to run through its agent.
+///
+/// Answer with [`VoiceTurn::respond`] once the agent has replied; the reply
+/// is spoken back. Dropping the turn without answering ends the conversation
+/// and the session waits for the wake word again.
+#[derive(Debug)]
+pub struct VoiceTurn {
+    /// The transcribed request, with any wake word removed.
+    pub text: String,
+    /// Whether the wake word was skipped because a conversation was open.
+    pub follow_up: bool,
+    reply: oneshot::Sender<String>,
+}
+
+impl VoiceTurn {
+    /// Send the agent's response back to be spoken.
+    pub fn respond(self, response: impl Into<String>) {
+        let _ = self.reply.send(response.into());
+    }
+}
+
+/// Result of asking the user to confirm an action by voice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoiceConfirmation {
+    /// The user said one of the configured confirmation phrases.
+    Confirmed,
+    /// Anything else. `heard` is the transcript, or `None` after silence.
+    Denied { heard: Option<String> },
+}
+
+struct ConfirmRequest {
+    prompt: String,
+    reply: oneshot::Sender<VoiceConfirmation>,
+}
+
+/// Cloneable handle for asking a running session to confirm an action.
+#[derive(Clone)]
+pub struct VoiceControl {
+    confirm_tx: mpsc::Sender<ConfirmRequest>,
+    turn_active: Arc<AtomicBool>,
+}
+
+impl VoiceControl {
+    /// Whether the host is handling a voice turn right now.
+    pub fn turn_in_progress(&self) -> bool {
+        self.turn_active.load(Ordering::SeqCst)
+    }
+
+    /// Speak `prompt` and listen for a confirmation phrase.
+    ///
+    /// Only answered while a turn is in progress. Returns `None` if the
+    /// session stopped before answering.
+    pub async fn confirm(&self, prompt: impl Into<String>) -> Option<VoiceConfirmation> {
+        let (reply, rx) = oneshot::channel();
+        self.confirm_tx
+            .send(ConfirmRequest {
+                prompt: prompt.into(),
+                reply,
+            })
+            .await
+            .ok()?;
+        rx.await.ok()
+    }
+}
+
 /// A non-blocking voice command session that listens for speech,
-/// transcribes it, and delivers the text via a callback.
+/// transcribes it, and hands each request to the host as a [`VoiceTurn`].
 pub struct VoiceCommandSession {
     cancel_tx: watch::Sender<bool>,
     handle: JoinHandle<()>,
     active: Arc<AtomicBool>,
+    control: VoiceControl,
 }
 
 impl VoiceCommandSession {
     /// Start a new voice command session in the background.
     ///
     /// The session records audio in chunks, detects speech via VAD,
-    /// transcribes via OpenAI Whisper, and sends the transcription text
-    /// through `on_transcription`. The response text can be spoken back
-    /// via `on_response`.
+    /// transcribes with the configured STT provider (OpenAI or local
+    /// Whisper), and sends each request through `turns`. Replies are spoken
+    /// with the configured TTS provider when one is available.
     pub async fn start(
         config: AgentConfig,
         _workspace: PathBuf,
-        on_transcription: Arc<dyn Fn(String) + Send + Sync>,
+        turns: mpsc::Sender<VoiceTurn>,
     ) -> Result<Self, VoiceError> {
-        let api_key = std::env::var("OPENAI_API_KEY").map_err(|_| VoiceError::AuthFailed {
-            provider: "openai".into(),
-        })?;
+        let voice_config = config.voice.clone().unwrap_or_default();
+        let stt = voice_stt_provider(&voice_config)?;
+        let tts = voice_tts_provider(&voice_config);
+        if tts.is_none() {
+            warn!("No TTS provider available — voice replies will not be spoken");
+        }
 
         let (cancel_tx, cancel_rx) = watch::channel(false);
+        let (confirm_tx, confirm_rx) = mpsc::channel(1);
         let active = Arc::new(AtomicBool::new(true));
-        let active_clone = active.clone();
-
-        let voice_config = config.voice.clone().unwrap_or_default();
-        let sample_rate: u32 = 16000;
-        let chunk_duration: f32 = 3.0;
+        let turn_active = Arc::new(AtomicBool::new(false));
 
+        let mut voice_loop = VoiceLoop {
+            config: voice_config,
+            stt,
+            tts,
+            cancel_rx,
+            turns,
+            confirm_rx,
+            turn_active: turn_active.clone(),
+        };
+        let active_clone = active.clone();
         let handle = tokio::spawn(async move {
-            voice_loop(
-                cancel_rx,
-                active_clone,
-                api_key,
-                voice_config,
-                sample_rate,
-                chunk_duration,
-                on_transcription,
-            )
-            .await;
+            voice_loop.run().await;
+            active_clone.store(false, Ordering::SeqCst);
+            info!("Voice loop exited");
         });
 
         info!("Voice command session started");
@@ -61,6 +155,10 @@ impl VoiceCommandSession {
             cancel_tx,
             handle,
             active,
+            control: VoiceControl {
+                confirm_tx,
+                turn_active,
+            },
         })
     }
 
@@ -70,7 +168,7 @@ impl VoiceCommandSession {
         let _ = self.cancel_tx.send(true);
         self.active.store(false, Ordering::SeqCst);
         // Wait for the background task to finish (with timeout).
-        let _ = tokio::time::timeout(std::time::Duration::from_secs(10), self.handle).await;
+        let _ = tokio::time::timeout(Duration::from_secs(10), self.handle).await;
         info!("Voice command session stopped");
         Ok(())
     }
@@ -79,128 +177,457 @@ impl VoiceCommandSession {
     pub fn is_active(&self) -> bool {
         self.active.load(Ordering::SeqCst)
     }
+
+    /// Handle for confirming actions by voice.
+    pub fn control(&self) -> VoiceControl {
+        self.control.clone()
+    }
 }
 
-/// The main voice command loop.
-async fn voice_loop(
-    mut cancel_rx: watch::Receiver<bool>,
-    active: Arc<AtomicBool>,
-    api_key: String,
-    voice_config: crate::config::VoiceConfig,
-    sample_rate: u32,
-    chunk_duration: f32,
-    on_transcription: Arc<dyn Fn(String) + Send + Sync>,
-) {
-    use crate::voice::audio_io::record_audio_chunk;
-    use crate::voice::stt::{OpenAiSttProvider, SttProvider};
-    use crate::voice::vad::{VadEvent, VoiceActivityDetector};
-
-    let stt = OpenAiSttProvider::new(&api_key);
-    let vad_threshold = voice_config.vad_threshold;
-    let mut vad = VoiceActivityDetector::new(vad_threshold);
-    let mut speech_buffer: Vec<f32> = Vec::new();
-    let mut is_collecting = false;
-    let max_collect_secs = voice_config.max_listen_secs as f32;
-    let max_collect_samples = (max_collect_secs * sample_rate as f32) as usize;
-
-    info!(
-        threshold = vad_threshold,
-        max_listen = voice_config.max_listen_secs,
-        "Voice loop started"
-    );
-
-    loop {
-        // Check for cancellation.
-        if *cancel_rx.borrow() {
-            debug!("Voice loop cancelled");
-            break;
-        }
-
-        // Record a chunk.
-        let chunk = tokio::select! {
-            result = record_audio_chunk(chunk_duration, sample_rate) => {
-                match result {
-                    Ok(c) => c,
-                    Err(e) => {
-                        warn!(error = %e, "Voice loop: failed to record chunk");
-                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+/// Choose the speech-to-text provider for voice commands.
+///
+/// `"whisper-local"` runs on-device and requires the `voice` feature and a
+/// downloaded model; anything else uses the OpenAI API with `OPENAI_API_KEY`.
+pub fn voice_stt_provider(config: &VoiceConfig) -> Result<Arc<dyn SttProvider>, VoiceError> {
+    if config.stt_provider == "whisper-local" {
+        #[cfg(feature = "voice")]
+        {
+            let whisper = crate::voice::stt::WhisperConfig {
+                model: config.stt_model.clone(),
+                ..Default::default()
+            };
+            let provider = crate::voice::stt::WhisperLocalProvider::from_config(
+                &whisper,
+                &config.stt_language,
+            );
+            if !provider.model_available() {
+                return Err(VoiceError::ModelNotFound {
+                    model: provider.model_path.clone(),
+                });
+            }
+            return Ok(Arc::new(provider));
+        }
+        #[cfg(not(feature = "voice"))]
+        return Err(VoiceError::FeatureNotEnabled);
+    }
+    let api_key = std::env::var("OPENAI_API_KEY").map_err(|_| VoiceError::AuthFailed {
+        provider: "openai".into(),
+    })?;
+    Ok(Arc::new(
+        OpenAiSttProvider::new(api_key).with_language(&config.stt_language),
+    ))
+}
+
+/// The TTS provider for spoken replies, if one is usable.
+fn voice_tts_provider(config: &VoiceConfig) -> Option<Arc<dyn TtsProvider>> {
+    match config.tts_provider.as_str() {
+        "mock" => Some(Arc::new(MockTtsProvider::new())),
+        _ => std::env::var("OPENAI_API_KEY")
+            .ok()
+            .map(|key| Arc::new(OpenAiTtsProvider::new(key)) as Arc<dyn TtsProvider>),
+    }
+}
+
+/// Whether the next utterance needs the wake word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Listening {
+    WakeWord,
+    FollowUp { until: Instant },
+}
+
+/// Turn counting for one voice conversation.
+struct Conversation {
+    follow_up: Duration,
+    max_turns: u32,
+    turns: u32,
+}
+
+impl Conversation {
+    fn new(config: &VoiceConfig) -> Self {
+        Self {
+            follow_up: Duration::from_secs(config.follow_up_secs),
+            max_turns: config.max_turns,
+            turns: 0,
+        }
+    }
+
+    /// Record an answered turn; returns how to listen next.
+    fn after_turn(&mut self, now: Instant) -> Listening {
+        self.turns += 1;
+        if self.follow_up.is_zero() || self.turns >= self.max_turns {
+            self.end()
+        } else {
+            Listening::FollowUp {
+                until: now + self.follow_up,
+            }
+        }
+    }
+
+    /// The wake word was said on its own; wait for the command without
+    /// counting a turn.
+    fn awaiting_command(&self, now: Instant) -> Listening {
+        Listening::FollowUp {
+            until: now + self.follow_up.max(MIN_COMMAND_WINDOW),
+        }
+    }
+
+    /// End the conversation; the wake word is needed again.
+    fn end(&mut self) -> Listening {
+        self.turns = 0;
+        Listening::WakeWord
+    }
+}
+
+enum Capture {
+    Speech(AudioChunk),
+    TimedOut,
+    Cancelled,
+}
+
+enum TurnOutcome {
+    Replied(String),
+    Dropped,
+    Stopped,
+}
+
+struct VoiceLoop {
+    config: VoiceConfig,
+    stt: Arc<dyn SttProvider>,
+    tts: Option<Arc<dyn TtsProvider>>,
+    cancel_rx: watch::Receiver<bool>,
+    turns: mpsc::Sender<VoiceTurn>,
+    confirm_rx: mpsc::Receiver<ConfirmRequest>,
+    turn_active: Arc<AtomicBool>,
+}
+
+impl VoiceLoop {
+    async fn run(&mut self) {
+        let mut conversation = Conversation::new(&self.config);
+        let mut listening = Listening::WakeWord;
+        let mut lead_in = None;
+
+        info!(
+            threshold = self.config.vad_threshold,
+            follow_up = self.config.follow_up_secs,
+            max_turns = self.config.max_turns,
+            "Voice loop started"
+        );
+
+        loop {
+            if *self.cancel_rx.borrow() {
+                debug!("Voice loop cancelled");
+                break;
+            }
+
+            let deadline = match listening {
+                Listening::WakeWord => None,
+                Listening::FollowUp { until } => Some(until),
+            };
+            let audio = match self.capture(lead_in.take(), deadline).await {
+                Capture::Speech(audio) => audio,
+                Capture::TimedOut => {
+                    debug!("Follow-up window closed");
+                    listening = conversation.end();
+                    continue;
+                }
+                Capture::Cancelled => break,
+            };
+            let Some(text) = self.transcribe(&audio).await else {
+                continue;
+            };
+
+            let follow_up = matches!(listening, Listening::FollowUp { .. });
+            let command = if follow_up {
+                text
+            } else {
+                match after_wake_word(&text, &self.config.wake_words) {
+                    Some(command) => command,
+                    None => {
+                        debug!(text = %text, "No wake word — ignoring");
                         continue;
                     }
                 }
+            };
+            if command.is_empty() {
+                listening = conversation.awaiting_command(Instant::now());
+                continue;
             }
-            _ = cancel_rx.changed() => {
-                debug!("Voice loop cancelled during recording");
-                break;
+
+            info!(text = %command, follow_up, "Voice turn");
+            match self.dispatch(command, follow_up).await {
+                TurnOutcome::Replied(reply) => {
+                    let spoken = spoken_reply(&reply);
+                    if !spoken.is_empty() {
+                        lead_in = self.speak(&spoken, self.config.barge_in).await;
+                    }
+                    listening = conversation.after_turn(Instant::now());
+                }
+                TurnOutcome::Dropped => listening = conversation.end(),
+                TurnOutcome::Stopped => break,
             }
-        };
+        }
+    }
+
+    /// Record until one utterance ends. `lead_in` is speech already heard
+    /// (a barge-in); `deadline` gives up if no speech has started by then.
+    async fn capture(&mut self, lead_in: Option<AudioChunk>, deadline: Option<Instant>) -> Capture {
+        let mut vad = VoiceActivityDetector::with_settings(self.config.vad_threshold, 1, 1, 2);
+        let max_samples = self.config.max_listen_secs as usize * SAMPLE_RATE as usize;
+        let mut speech: Vec<f32> = Vec::new();
+        let mut pending = lead_in;
+
+        loop {
+            let chunk = match pending.take() {
+                Some(chunk) => chunk,
+                None => {
+                    if !vad.is_speaking() && deadline.is_some_and(|d| Instant::now() >= d) {
+                        return Capture::TimedOut;
+                    }
+                    tokio::select! {
+                        result = record_audio_chunk(CHUNK_SECS, SAMPLE_RATE) => match result {
+                            Ok(chunk) => chunk,
+                            Err(e) => {
+                                warn!(error = %e, "Voice loop: failed to record chunk");
+                                tokio::time::sleep(Duration::from_secs(1)).await;
+                                continue;
+                            }
+                        },
+                        _ = self.cancel_rx.changed() => return Capture::Cancelled,
+                    }
+                }
+            };
 
-        // Process through VAD.
-        let event = vad.process_chunk(&chunk);
-
-        match event {
-            VadEvent::SpeechStart => {
-                debug!("Speech detected — collecting audio");
-                is_collecting = true;
-                speech_buffer.clear();
-                speech_buffer.extend_from_slice(&chunk.samples);
-            }
-            VadEvent::SpeechEnd if is_collecting => {
-                // Add final chunk and transcribe.
-                speech_buffer.extend_from_slice(&chunk.samples);
-                is_collecting = false;
-
-                let audio = crate::voice::types::AudioChunk::new(
-                    std::mem::take(&mut speech_buffer),
-                    sample_rate,
-                    1,
-                );
-
-                debug!(
-                    duration = audio.duration_secs(),
-                    "Speech ended — transcribing"
-                );
-
-                match stt.transcribe(&audio).await {
-                    Ok(result) if !result.text.trim().is_empty() => {
-                        info!(text = %result.text, "Transcription received");
-                        on_transcription(result.text);
+            match vad.process_chunk(&chunk) {
+                VadEvent::SpeechStart => {
+                    speech.clear();
+                    speech.extend_from_slice(&chunk.samples);
+                }
+                VadEvent::SpeechEnd => {
+                    speech.extend_from_slice(&chunk.samples);
+                    return Capture::Speech(AudioChunk::new(speech, SAMPLE_RATE, 1));
+                }
+                VadEvent::NoChange if vad.is_speaking() => {
+                    speech.extend_from_slice(&chunk.samples);
+                    if speech.len() >= max_samples {
+                        debug!("Max listen reached");
+                        return Capture::Speech(AudioChunk::new(speech, SAMPLE_RATE, 1));
                     }
-                    Ok(_) => debug!("Empty transcription — ignoring"),
-                    Err(e) => warn!(error = %e, "Transcription failed"),
                 }
+                VadEvent::NoChange => {}
             }
-            VadEvent::NoChange if is_collecting => {
-                speech_buffer.extend_from_slice(&chunk.samples);
-                // Enforce max collection length.
-                if speech_buffer.len() >= max_collect_samples {
-                    is_collecting = false;
-                    let audio = crate::voice::types::AudioChunk::new(
-                        std::mem::take(&mut speech_buffer),
-                        sample_rate,
-                        1,
-                    );
-                    debug!(
-                        duration = audio.duration_secs(),
-                        "Max listen reached — transcribing"
-                    );
-                    match stt.transcribe(&audio).await {
-                        Ok(result) if !result.text.trim().is_empty() => {
-                            info!(text = %result.text, "Transcription received (max length)");
-                            on_transcription(result.text);
-                        }
-                        Ok(_) => debug!("Empty transcription — ignoring"),
-                        Err(e) => warn!(error = %e, "Transcription failed"),
+        }
+    }
+
+    async fn transcribe(&self, audio: &AudioChunk) -> Option<String> {
+        debug!(
+            duration = audio.duration_secs(),
+            "Speech ended — transcribing"
+        );
+        match self.stt.transcribe(audio).await {
+            Ok(result) if !result.text.trim().is_empty() => {
+                info!(text = %result.text, "Transcription received");
+                Some(result.text.trim().to_string())
+            }
+            Ok(_) => {
+                debug!("Empty transcription — ignoring");
+                None
+            }
+            Err(e) => {
+                warn!(error = %e, "Transcription failed");
+                None
+            }
+        }
+    }
+
+    /// Hand a turn to the host and wait for its reply, answering voice
+    /// confirmations for actions the turn triggers in the meantime.
+    async fn dispatch(&mut self, text: String, follow_up: bool) -> TurnOutcome {
+        let (reply, mut reply_rx) = oneshot::channel();
+        let turn = VoiceTurn {
+            text,
+            follow_up,
+            reply,
+        };
+        if self.turns.send(turn).await.is_err() {
+            debug!("Voice turn receiver closed");
+            return TurnOutcome::Stopped;
+        }
+
+        self.turn_active.store(true, Ordering::SeqCst);
+        let outcome = loop {
+            tokio::select! {
+                reply = &mut reply_rx => break match reply {
+                    Ok(text) => TurnOutcome::Replied(text),
+                    Err(_) => TurnOutcome::Dropped,
+                },
+                Some(request) = self.confirm_rx.recv() => {
+                    let confirmation = self.confirm(&request.prompt).await;
+                    let _ = request.reply.send(confirmation);
+                }
+                _ = self.cancel_rx.changed() => break TurnOutcome::Stopped,
+            }
+        };
+        self.turn_active.store(false, Ordering::SeqCst);
+        // Requests that raced the end of the turn are dropped, which their
+        // callers treat as a denial.
+        while self.confirm_rx.try_recv().is_ok() {}
+        outcome
+    }
+
+    /// Speak `prompt` and listen for one of the confirmation phrases.
+    async fn confirm(&mut self, prompt: &str) -> VoiceConfirmation {
+        let Some(phrase) = self.config.confirm_phrases.first() else {
+            return VoiceConfirmation::Denied { heard: None };
+        };
+        let spoken = format!("{prompt}. Say \"{phrase}\" to go ahead.");
+        self.speak(&spoken, false).await;
+
+        let deadline = Instant::now() + Duration::from_secs(self.config.confirm_timeout_secs);
+        let heard = match self.capture(None, Some(deadline)).await {
+            Capture::Speech(audio) => self.transcribe(&audio).await,
+            Capture::TimedOut | Capture::Cancelled => None,
+        };
+        match heard {
+            Some(text) if matches_confirm_phrase(&text, &self.config.confirm_phrases) => {
+                info!("Action confirmed by voice");
+                VoiceConfirmation::Confirmed
+            }
+            heard => {
+                info!(heard = ?heard, "Action not confirmed by voice");
+                VoiceConfirmation::Denied { heard }
+            }
+        }
+    }
+
+    /// Speak `text`. With `barge_in`, listen while it plays and return the
+    /// user's speech if they interrupt, as the start of the next utterance.
+    async fn speak(&mut self, text: &str, barge_in: bool) -> Option<AudioChunk> {
+        let tts = self.tts.clone()?;
+        let request = SynthesisRequest::new(text)
+            .with_voice(&self.config.tts_voice)
+            .with_speed(self.config.tts_speed);
+        let speech = match tts.synthesize(&request).await {
+            Ok(result) => result.audio,
+            Err(e) => {
+                warn!(error = %e, "Speech synthesis failed");
+                return None;
+            }
+        };
+
+        let playback = play_audio(&speech);
+        tokio::pin!(playback);
+        let mut vad = VoiceActivityDetector::with_settings(
+            self.config.vad_threshold * BARGE_IN_THRESHOLD_FACTOR,
+            1,
+            1,
+            2,
+        );
+        loop {
+            tokio::select! {
+                result = &mut playback => {
+                    if let Err(e) = result {
+                        warn!(error = %e, "Playback failed");
                     }
+                    return None;
                 }
+                chunk = record_audio_chunk(CHUNK_SECS, SAMPLE_RATE), if barge_in => match chunk {
+                    Ok(chunk) if vad.process_chunk(&chunk) == VadEvent::SpeechStart => {
+                        info!("Barge-in — stopping playback");
+                        return Some(chunk);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!(error = %e, "Barge-in listening failed");
+                        if let Err(e) = (&mut playback).await {
+                            warn!(error = %e, "Playback failed");
+                        }
+                        return None;
+                    }
+                },
+                _ = self.cancel_rx.changed() => return None,
             }
-            _ => {
-                // Silence or speech continuing without change — do nothing special.
+        }
+    }
+}
+
+/// Lowercase and reduce to words so transcripts compare reliably.
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                ' '
             }
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The command after a wake word, or `None` when no wake word was said.
+/// Empty when the wake word was said on its own. With no wake words
+/// configured every utterance is a command.
+fn after_wake_word(text: &str, wake_words: &[String]) -> Option<String> {
+    if wake_words.is_empty() {
+        return Some(text.trim().to_string());
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = words.iter().map(|w| normalize(w)).collect();
+    for wake in wake_words {
+        let wake = normalize(wake);
+        let wake: Vec<&str> = wake.split(' ').collect();
+        if wake.iter().all(|w| w.is_empty()) || wake.len() > normalized.len() {
+            continue;
+        }
+        if let Some(start) = normalized
+            .windows(wake.len())
+            .position(|window| window.iter().zip(&wake).all(|(a, b)| a == b))
+        {
+            let rest = words[start + wake.len()..].join(" ");
+            return Some(
+                rest.trim_start_matches(|c: char| !c.is_alphanumeric())
+                    .to_string(),
+            );
         }
     }
+    None
+}
 
-    active.store(false, Ordering::SeqCst);
-    info!("Voice loop exited");
+/// Whether `heard` is exactly one of the confirmation phrases, ignoring
+/// case and punctuation. Longer or partial answers do not count.
+fn matches_confirm_phrase(heard: &str, phrases: &[String]) -> bool {
+    let heard = normalize(heard);
+    !heard.is_empty() && phrases.iter().any(|p| normalize(p) == heard)
+}
+
+/// Reply text suitable for speech: code blocks are left out and markdown
+/// markers dropped.
+fn spoken_reply(reply: &str) -> String {
+    let mut spoken = Vec::new();
+    let mut in_code = false;
+    let mut skipped_code = false;
+    for line in reply.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            if in_code && !skipped_code {
+                spoken.push("(code omitted)".to_string());
+                skipped_code = true;
+            }
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        let line = line
+            .trim_start_matches(|c: char| c == '#' || c == '>' || c.is_whitespace())
+            .replace(['*', '`', '_'], "");
+        if !line.trim().is_empty() {
+            spoken.push(line.trim().to_string());
+        }
+    }
+    spoken.join(" ")
 }
 
 #[cfg(test)]
@@ -214,4 +641,75 @@ mod tests {
         active.store(false, Ordering::SeqCst);
         assert!(!active.load(Ordering::SeqCst));
     }
+
+    #[test]
+    fn test_after_wake_word() {
+        let wake = vec!["hey rustant".to_string()];
+        assert_eq!(
+            after_wake_word("Hey, Rustant! What's on my calendar?", &wake).as_deref(),
+            Some("What's on my calendar?")
+        );
+        assert_eq!(after_wake_word("hey rustant.", &wake).as_deref(), Some(""));
+        assert_eq!(after_wake_word("what time is it", &wake), None);
+        assert_eq!(
+            after_wake_word("what time is it", &[]).as_deref(),
+            Some("what time is it")
+        );
+    }
+
+    #[test]
+    fn test_confirm_phrase_is_exact() {
+        let phrases = vec!["confirm".to_string(), "go ahead".to_string()];
+        assert!(matches_confirm_phrase("Confirm.", &phrases));
+        assert!(matches_confirm_phrase("Go ahead!", &phrases));
+        assert!(!matches_confirm_phrase("don't confirm", &phrases));
+        assert!(!matches_confirm_phrase("yes", &phrases));
+        assert!(!matches_confirm_phrase("", &phrases));
+        assert!(!matches_confirm_phrase("confirm", &[]));
+    }
+
+    #[test]
+    fn test_conversation_follow_up_and_turn_limit() {
+        let config = VoiceConfig {
+            follow_up_secs: 8,
+            max_turns: 2,
+            ..Default::default()
+        };
+        let mut conversation = Conversation::new(&config);
+        let now = Instant::now();
+        assert_eq!(
+            conversation.after_turn(now),
+            Listening::FollowUp {
+                until: now + Duration::from_secs(8)
+            }
+        );
+        assert_eq!(conversation.after_turn(now), Listening::WakeWord);
+        // The limit applies per conversation.
+        assert!(matches!(
+            conversation.after_turn(now),
+            Listening::FollowUp { .. }
+        ));
+
+        let mut no_follow_up = Conversation::new(&VoiceConfig {
+            follow_up_secs: 0,
+            ..Default::default()
+        });
+        assert_eq!(no_follow_up.after_turn(now), Listening::WakeWord);
+        assert_eq!(
+            no_follow_up.awaiting_command(now),
+            Listening::FollowUp {
+                until: now + MIN_COMMAND_WINDOW
+            }
+        );
+    }
+
+    #[test]
+    fn test_spoken_reply_skips_code() {
+        let reply =
+            "## Done\nI updated **main.rs**:\n```rust\nfn main() {}\n```\nRun `cargo test`.";
+        assert_eq!(
+            spoken_reply(reply),
+            "Done I updated main.rs: (code omitted) Run cargo test."
+        );
+    }
 }
@@ -0,0 +1,439 @@
+//! Streaming transcription of a live audio feed.
+//!
+//! [`StreamingTranscriber`] buffers incoming [`AudioChunk`]s and transcribes
+//! them in fixed windows while recording is still running, emitting
+//! [`TranscriptionSegment`]s with timestamps on the recording's timeline.
+//! Segments are optionally labelled by a [`SpeakerDiarizer`].
+//!
+//! The transcriber tracks how far behind real time it is. When that lag
+//! exceeds `max_lag_secs`, or the provider fails, it stops transcribing live,
+//! keeps buffering audio, and transcribes the remainder in one batch from
+//! [`finish`](StreamingTranscriber::finish). The switch is recorded as
+//! [`TranscriptionMode::FellBackToBatch`] so callers can report it.
+
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use super::diarization::SpeakerDiarizer;
+use super::stt::SttProvider;
+use super::types::{AudioChunk, TranscriptionSegment};
+use crate::error::VoiceError;
+
+/// Default length of audio transcribed per live window.
+pub const DEFAULT_WINDOW_SECS: f32 = 5.0;
+/// Default lag behind real time tolerated before falling back to batch.
+pub const DEFAULT_MAX_LAG_SECS: f32 = 15.0;
+
+/// How a transcript was produced.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum TranscriptionMode {
+    /// Transcribed after recording stopped.
+    #[default]
+    Batch,
+    /// Transcribed live while recording.
+    Live,
+    /// Started live, then fell back to batch transcription at `at_secs`.
+    FellBackToBatch { reason: String, at_secs: f32 },
+}
+
+impl std::fmt::Display for TranscriptionMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Batch => write!(f, "batch"),
+            Self::Live => write!(f, "live"),
+            Self::FellBackToBatch { reason, at_secs } => write!(
+                f,
+                "live until {}, then batch ({})",
+                format_timestamp(*at_secs),
+                reason
+            ),
+        }
+    }
+}
+
+/// Segments transcribed so far and how they were produced.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LiveTranscript {
+    pub segments: Vec<TranscriptionSegment>,
+    pub mode: TranscriptionMode,
+}
+
+impl LiveTranscript {
+    /// The most recent `n` segments.
+    pub fn recent(&self, n: usize) -> &[TranscriptionSegment] {
+        &self.segments[self.segments.len().saturating_sub(n)..]
+    }
+}
+
+/// Transcribes an audio feed in windows as it arrives.
+pub struct StreamingTranscriber {
+    provider: Arc<dyn SttProvider>,
+    diarizer: Option<SpeakerDiarizer>,
+    window_secs: f32,
+    max_lag_secs: f32,
+    /// Audio received but not yet transcribed.
+    pending: Option<AudioChunk>,
+    /// Recording-timeline position of the first pending sample.
+    pending_start_secs: f32,
+    /// Seconds of processing time not yet covered by audio duration.
+    lag_secs: f32,
+    /// Seconds of audio received so far.
+    received_secs: f32,
+    transcript: Arc<Mutex<LiveTranscript>>,
+}
+
+impl StreamingTranscriber {
+    /// Create a transcriber using `provider` for each window.
+    pub fn new(provider: Arc<dyn SttProvider>) -> Self {
+        Self {
+            provider,
+            diarizer: None,
+            window_secs: DEFAULT_WINDOW_SECS,
+            max_lag_secs: DEFAULT_MAX_LAG_SECS,
+            pending: None,
+            pending_start_secs: 0.0,
+            lag_secs: 0.0,
+            received_secs: 0.0,
+            transcript: Arc::new(Mutex::new(LiveTranscript {
+                segments: Vec::new(),
+                mode: TranscriptionMode::Live,
+            })),
+        }
+    }
+
+    /// Label segments with speakers using `diarizer`.
+    pub fn with_diarizer(mut self, diarizer: SpeakerDiarizer) -> Self {
+        self.diarizer = Some(diarizer);
+        self
+    }
+
+    /// Set the window length in seconds.
+    pub fn with_window_secs(mut self, secs: f32) -> Self {
+        self.window_secs = secs.max(0.1);
+        self
+    }
+
+    /// Set how far behind real time transcription may fall before switching to batch.
+    pub fn with_max_lag_secs(mut self, secs: f32) -> Self {
+        self.max_lag_secs = secs.max(0.0);
+        self
+    }
+
+    /// A shared view of the transcript, updated as windows complete.
+    pub fn transcript(&self) -> Arc<Mutex<LiveTranscript>> {
+        Arc::clone(&self.transcript)
+    }
+
+    /// Seconds of audio received so far.
+    pub fn received_secs(&self) -> f32 {
+        self.received_secs
+    }
+
+    /// Whether windows are still being transcribed live.
+    pub fn is_live(&self) -> bool {
+        self.transcript.lock().unwrap().mode == TranscriptionMode::Live
+    }
+
+    /// Add captured audio, transcribing any complete windows.
+    ///
+    /// Returns the segments produced by this call.
+    pub async fn push(&mut self, chunk: &AudioChunk) -> Vec<TranscriptionSegment> {
+        if chunk.is_empty() {
+            return Vec::new();
+        }
+        match &mut self.pending {
+            Some(pending)
+                if pending.sample_rate == chunk.sample_rate
+                    && pending.channels == chunk.channels =>
+            {
+                pending.append(chunk)
+            }
+            Some(_) => {
+                warn!("Audio format changed mid-stream; dropping chunk");
+                return Vec::new();
+            }
+            None => self.pending = Some(chunk.clone()),
+        }
+        self.received_secs += chunk.duration_secs();
+
+        let mut emitted = Vec::new();
+        while self.is_live() {
+            let Some(pending) = &self.pending else { break };
+            let window_frames = (self.window_secs * pending.sample_rate as f32) as usize;
+            if pending.num_frames() < window_frames {
+                break;
+            }
+            let (window, rest) = pending.split_at(window_frames * pending.channels as usize);
+            self.pending = (!rest.is_empty()).then_some(rest);
+
+            let window_start = self.pending_start_secs;
+            let started = Instant::now();
+            match self.transcribe(&window, window_start).await {
+                Ok(segments) => {
+                    let audio_secs = window.duration_secs();
+                    self.pending_start_secs += audio_secs;
+                    self.lag_secs =
+                        (self.lag_secs + started.elapsed().as_secs_f32() - audio_secs).max(0.0);
+                    debug!(
+                        window_start,
+                        lag_secs = self.lag_secs,
+                        segments = segments.len(),
+                        "Live transcription window"
+                    );
+                    emitted.extend(segments);
+                    if self.lag_secs > self.max_lag_secs {
+                        self.fall_back(format!(
+                            "transcription fell {:.0}s behind real time",
+                            self.lag_secs
+                        ));
+                    }
+                }
+                Err(e) => {
+                    // Keep the window's audio for the batch pass.
+                    self.pending = Some(match self.pending.take() {
+                        Some(rest) => {
+                            let mut window = window;
+                            window.append(&rest);
+                            window
+                        }
+                        None => window,
+                    });
+                    self.fall_back(format!("live transcription failed: {}", e));
+                }
+            }
+        }
+        emitted
+    }
+
+    /// Transcribe the remaining audio and return the complete transcript.
+    pub async fn finish(mut self) -> Result<LiveTranscript, VoiceError> {
+        if let Some(rest) = self.pending.take() {
+            let start = self.pending_start_secs;
+            self.transcribe(&rest, start).await?;
+        }
+        let transcript = self.transcript.lock().unwrap().clone();
+        Ok(transcript)
+    }
+
+    fn fall_back(&mut self, reason: String) {
+        warn!(
+            at_secs = self.pending_start_secs,
+            "{}; switching to batch transcription", reason
+        );
+        self.transcript.lock().unwrap().mode = TranscriptionMode::FellBackToBatch {
+            reason,
+            at_secs: self.pending_start_secs,
+        };
+    }
+
+    /// Transcribe `audio`, which starts at `start_secs`, and record its segments.
+    async fn transcribe(
+        &mut self,
+        audio: &AudioChunk,
+        start_secs: f32,
+    ) -> Result<Vec<TranscriptionSegment>, VoiceError> {
+        let result = self.provider.transcribe(audio).await?;
+        let mut segments: Vec<TranscriptionSegment> = if result.segments.is_empty() {
+            // Providers without segment timing: one segment for the window.
+            let text = result.text.trim();
+            if text.is_empty() {
+                Vec::new()
+            } else {
+                vec![TranscriptionSegment {
+                    text: text.to_string(),
+                    start_secs: 0.0,
+                    end_secs: audio.duration_secs(),
+                    confidence: result.confidence,
+                    speaker: None,
+                }]
+            }
+        } else {
+            result
+                .segments
+                .into_iter()
+                .filter(|s| !s.text.trim().is_empty())
+                .collect()
+        };
+        segments = segments.into_iter().map(|s| s.offset(start_secs)).collect();
+        if let Some(diarizer) = &mut self.diarizer {
+            diarizer.label_segments(audio, start_secs, &mut segments);
+        }
+        self.transcript
+            .lock()
+            .unwrap()
+            .segments
+            .extend(segments.iter().cloned());
+        Ok(segments)
+    }
+}
+
+/// Format seconds as `mm:ss` (or `h:mm:ss` past an hour).
+pub fn format_timestamp(secs: f32) -> String {
+    let total = secs.max(0.0) as u64;
+    let (h, m, s) = (total / 3600, (total % 3600) / 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
+    }
+}
+
+/// Render segments as a transcript, one line per speaker turn.
+///
+/// Consecutive segments from the same speaker are merged; each turn starts
+/// with its timestamp and, when diarized, the speaker label.
+pub fn attributed_transcript(segments: &[TranscriptionSegment]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current: Option<&Option<String>> = None;
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        if current == Some(&segment.speaker)
+            && let Some(line) = lines.last_mut()
+        {
+            line.push(' ');
+            line.push_str(text);
+            continue;
+        }
+        current = Some(&segment.speaker);
+        let stamp = format_timestamp(segment.start_secs);
+        lines.push(match &segment.speaker {
+            Some(speaker) => format!("[{}] {}: {}", stamp, speaker, text),
+            None => format!("[{}] {}", stamp, text),
+        });
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voice::stt::MockSttProvider;
+    use crate::voice::types::TranscriptionResult;
+    use async_trait::async_trait;
+
+    fn words(text: &str) -> TranscriptionResult {
+        TranscriptionResult {
+            text: text.into(),
+            confidence: 0.9,
+            ..Default::default()
+        }
+    }
+
+    fn second_of_audio() -> AudioChunk {
+        AudioChunk::silence(16000, 1, 16000)
+    }
+
+    /// A provider slower than real time.
+    struct SlowStt;
+
+    #[async_trait]
+    impl SttProvider for SlowStt {
+        async fn transcribe(&self, _audio: &AudioChunk) -> Result<TranscriptionResult, VoiceError> {
+            tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+            Ok(words("slow"))
+        }
+
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn is_offline(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_windows_emit_segments_on_recording_timeline() {
+        let mock = MockSttProvider::with_responses(vec![
+            words("first window"),
+            words("second window"),
+            words("tail"),
+        ]);
+        let mut transcriber = StreamingTranscriber::new(Arc::new(mock)).with_window_secs(2.0);
+
+        assert!(transcriber.push(&second_of_audio()).await.is_empty());
+        let first = transcriber.push(&second_of_audio()).await;
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].text, "first window");
+        assert!((first[0].end_secs - 2.0).abs() < f32::EPSILON);
+
+        transcriber.push(&second_of_audio()).await;
+        let second = transcriber.push(&second_of_audio()).await;
+        assert!((second[0].start_secs - 2.0).abs() < f32::EPSILON);
+
+        let view = transcriber.transcript();
+        transcriber.push(&second_of_audio()).await;
+        assert_eq!(view.lock().unwrap().segments.len(), 2);
+
+        let transcript = transcriber.finish().await.unwrap();
+        assert_eq!(transcript.mode, TranscriptionMode::Live);
+        assert_eq!(transcript.segments.len(), 3);
+        assert!((transcript.segments[2].start_secs - 4.0).abs() < f32::EPSILON);
+        assert!((transcript.segments[2].end_secs - 5.0).abs() < f32::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_batch_when_behind_real_time() {
+        // 0.1s windows that take 150ms each.
+        let mut transcriber = StreamingTranscriber::new(Arc::new(SlowStt))
+            .with_window_secs(0.1)
+            .with_max_lag_secs(0.0);
+        let first = transcriber.push(&AudioChunk::silence(16000, 1, 1600)).await;
+        assert_eq!(first.len(), 1);
+        assert!(!transcriber.is_live());
+
+        // Later audio is buffered, not transcribed, until finish().
+        assert!(transcriber.push(&second_of_audio()).await.is_empty());
+        let transcript = transcriber.finish().await.unwrap();
+        match &transcript.mode {
+            TranscriptionMode::FellBackToBatch { reason, at_secs } => {
+                assert!(reason.contains("behind real time"));
+                assert!((*at_secs - 0.1).abs() < 1e-4);
+            }
+            other => panic!("expected fallback, got {:?}", other),
+        }
+        assert_eq!(transcript.segments.len(), 2);
+        assert!((transcript.segments[1].start_secs - 0.1).abs() < 1e-4);
+    }
+
+    #[tokio::test]
+    async fn test_provider_failure_keeps_audio_for_batch() {
+        // A mock with no queued responses fails every call.
+        let mut transcriber =
+            StreamingTranscriber::new(Arc::new(MockSttProvider::new())).with_window_secs(1.0);
+        transcriber.push(&second_of_audio()).await;
+        assert!(!transcriber.is_live());
+        assert_eq!(transcriber.pending.as_ref().unwrap().num_frames(), 16000);
+        assert!(transcriber.finish().await.is_err());
+    }
+
+    #[test]
+    fn test_attributed_transcript_merges_speaker_turns() {
+        let seg = |start: f32, speaker: Option<&str>, text: &str| TranscriptionSegment {
+            text: text.into(),
+            start_secs: start,
+            end_secs: start + 1.0,
+            confidence: 1.0,
+            speaker: speaker.map(String::from),
+        };
+        let segments = vec![
+            seg(0.0, Some("Speaker 1"), "Let's ship Friday."),
+            seg(2.0, Some("Speaker 1"), "Alice owns the release."),
+            seg(65.0, Some("Speaker 2"), "I'll write the notes."),
+            seg(3700.0, None, "Bye."),
+        ];
+        assert_eq!(
+            attributed_transcript(&segments),
+            "[00:00] Speaker 1: Let's ship Friday. Alice owns the release.\n\
+             [01:05] Speaker 2: I'll write the notes.\n\
+             [1:01:40] Bye."
+        );
+    }
+}
@@ -1,13 +1,16 @@
 //! Speech-to-text provider trait and implementations.
 //!
-//! The `SttProvider` trait, `MockSttProvider`, and `OpenAiSttProvider` are
-//! always available. `WhisperLocalProvider` requires the `voice` feature.
+//! The `SttProvider` trait, `MockSttProvider`, `OpenAiSttProvider` and
+//! `WhisperConfig` are always available. `WhisperLocalProvider` requires the
+//! `voice` feature.
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
-use super::types::{AudioChunk, TranscriptionResult};
+use super::types::{AudioChunk, TranscriptionResult, TranscriptionSegment};
 use crate::error::VoiceError;
 
 /// Trait for speech-to-text providers.
@@ -178,17 +181,7 @@ impl SttProvider for OpenAiSttProvider {
                     message: format!("JSON parse error: {}", e),
                 })?;
 
-        let text = json["text"].as_str().unwrap_or("").to_string();
-        let language = json["language"].as_str().map(|s| s.to_string());
-        let duration = json["duration"].as_f64().unwrap_or(0.0) as f32;
-
-        Ok(TranscriptionResult {
-            text,
-            confidence: 1.0, // OpenAI doesn't return per-result confidence
-            language,
-            segments: Vec::new(),
-            duration_secs: duration,
-        })
+        Ok(parse_verbose_json(&json))
     }
 
     fn name(&self) -> &str {
@@ -200,37 +193,244 @@ impl SttProvider for OpenAiSttProvider {
     }
 }
 
+/// Parse an OpenAI `verbose_json` transcription response.
+fn parse_verbose_json(json: &serde_json::Value) -> TranscriptionResult {
+    let segments = json["segments"]
+        .as_array()
+        .map(|segments| {
+            segments
+                .iter()
+                .map(|seg| TranscriptionSegment {
+                    text: seg["text"].as_str().unwrap_or("").trim().to_string(),
+                    start_secs: seg["start"].as_f64().unwrap_or(0.0) as f32,
+                    end_secs: seg["end"].as_f64().unwrap_or(0.0) as f32,
+                    // avg_logprob is a mean log-probability; map it back to 0..1.
+                    confidence: seg["avg_logprob"]
+                        .as_f64()
+                        .map_or(1.0, |p| p.exp().clamp(0.0, 1.0) as f32),
+                    speaker: None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    TranscriptionResult {
+        text: json["text"].as_str().unwrap_or("").to_string(),
+        confidence: 1.0, // OpenAI doesn't return per-result confidence
+        language: json["language"].as_str().map(|s| s.to_string()),
+        segments,
+        duration_secs: json["duration"].as_f64().unwrap_or(0.0) as f32,
+    }
+}
+
+/// Where local Whisper inference runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComputeDevice {
+    /// Use the GPU when whisper.cpp was built with GPU support, else the CPU.
+    #[default]
+    Auto,
+    /// Always run on the CPU.
+    Cpu,
+    /// Request the GPU (Metal / CUDA builds of whisper.cpp).
+    Gpu,
+}
+
+/// Local Whisper model settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WhisperConfig {
+    /// Model size: "tiny", "base", "small", "medium", "large".
+    pub model: String,
+    /// Explicit ggml model file; overrides `model`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_path: Option<PathBuf>,
+    /// Compute device for inference.
+    pub device: ComputeDevice,
+    /// Inference threads (0 = number of cores, capped at 8).
+    pub threads: usize,
+}
+
+impl Default for WhisperConfig {
+    fn default() -> Self {
+        Self {
+            model: "base".to_string(),
+            model_path: None,
+            device: ComputeDevice::Auto,
+            threads: 0,
+        }
+    }
+}
+
+impl WhisperConfig {
+    /// The model file to load: `model_path`, or `ggml-<model>.bin` under the
+    /// data directory's `models/` folder.
+    pub fn resolve_model_path(&self) -> PathBuf {
+        if let Some(path) = &self.model_path {
+            return path.clone();
+        }
+        let file = format!("ggml-{}.bin", self.model);
+        directories::ProjectDirs::from("dev", "rustant", "rustant")
+            .map(|d| d.data_dir().join("models").join(&file))
+            .unwrap_or_else(|| PathBuf::from(".rustant").join("models").join(file))
+    }
+
+    /// Inference thread count after applying the `0 = auto` default.
+    pub fn thread_count(&self) -> usize {
+        match self.threads {
+            0 => std::thread::available_parallelism()
+                .map_or(4, |n| n.get())
+                .min(8),
+            n => n,
+        }
+    }
+}
+
 /// Whisper-rs local STT provider (requires `voice` feature).
+///
+/// The model is loaded on first use and shared by later calls. Audio is
+/// downmixed to mono and resampled to 16 kHz before inference, which runs on
+/// a blocking thread. Segment timestamps are relative to the chunk start.
 #[cfg(feature = "voice")]
 pub struct WhisperLocalProvider {
     /// Path to the Whisper model file.
     pub model_path: String,
     /// Language hint.
     pub language: String,
+    /// Compute device for inference.
+    pub device: ComputeDevice,
+    /// Inference threads.
+    pub threads: usize,
+    context: Mutex<Option<std::sync::Arc<whisper_rs::WhisperContext>>>,
 }
 
 #[cfg(feature = "voice")]
 impl WhisperLocalProvider {
+    /// Sample rate Whisper models expect.
+    const SAMPLE_RATE: u32 = 16000;
+
     /// Create a new local Whisper provider.
     pub fn new(model_path: impl Into<String>, language: impl Into<String>) -> Self {
         Self {
             model_path: model_path.into(),
             language: language.into(),
+            device: ComputeDevice::Auto,
+            threads: WhisperConfig::default().thread_count(),
+            context: Mutex::new(None),
         }
     }
+
+    /// Create a provider from model settings.
+    pub fn from_config(config: &WhisperConfig, language: impl Into<String>) -> Self {
+        Self {
+            device: config.device,
+            threads: config.thread_count(),
+            ..Self::new(
+                config.resolve_model_path().to_string_lossy().into_owned(),
+                language,
+            )
+        }
+    }
+
+    /// Whether the model file exists on disk.
+    pub fn model_available(&self) -> bool {
+        std::path::Path::new(&self.model_path).is_file()
+    }
+
+    fn context(&self) -> Result<std::sync::Arc<whisper_rs::WhisperContext>, VoiceError> {
+        let mut guard = self.context.lock().unwrap();
+        if let Some(ctx) = guard.as_ref() {
+            return Ok(std::sync::Arc::clone(ctx));
+        }
+        if !self.model_available() {
+            return Err(VoiceError::ModelNotFound {
+                model: self.model_path.clone(),
+            });
+        }
+        let mut params = whisper_rs::WhisperContextParameters::default();
+        params.use_gpu(self.device != ComputeDevice::Cpu);
+        let ctx =
+            whisper_rs::WhisperContext::new_with_params(&self.model_path, params).map_err(|e| {
+                VoiceError::TranscriptionFailed {
+                    message: format!("Failed to load Whisper model {}: {}", self.model_path, e),
+                }
+            })?;
+        let ctx = std::sync::Arc::new(ctx);
+        *guard = Some(std::sync::Arc::clone(&ctx));
+        Ok(ctx)
+    }
 }
 
 #[cfg(feature = "voice")]
 #[async_trait]
 impl SttProvider for WhisperLocalProvider {
     async fn transcribe(&self, audio: &AudioChunk) -> Result<TranscriptionResult, VoiceError> {
+        use super::audio_io::audio_convert;
+
         if audio.is_empty() {
             return Ok(TranscriptionResult::default());
         }
-        // In a real implementation, this would use whisper-rs to transcribe.
-        // For now, return an error indicating the model is not loaded.
-        Err(VoiceError::ModelNotFound {
-            model: self.model_path.clone(),
+        let ctx = self.context()?;
+
+        let mono = if audio.channels == 2 {
+            audio_convert::stereo_to_mono(&audio.samples)
+        } else {
+            audio.samples.clone()
+        };
+        let samples = audio_convert::resample(&mono, audio.sample_rate, Self::SAMPLE_RATE);
+        let duration_secs = samples.len() as f32 / Self::SAMPLE_RATE as f32;
+        let language = self.language.clone();
+        let threads = self.threads as i32;
+
+        let failed = |e: whisper_rs::WhisperError| VoiceError::TranscriptionFailed {
+            message: e.to_string(),
+        };
+        let segments = tokio::task::spawn_blocking(move || {
+            let mut state = ctx.create_state().map_err(failed)?;
+            let mut params =
+                whisper_rs::FullParams::new(whisper_rs::SamplingStrategy::Greedy { best_of: 1 });
+            params.set_language(Some(&language));
+            params.set_n_threads(threads);
+            params.set_print_special(false);
+            params.set_print_progress(false);
+            params.set_print_realtime(false);
+            params.set_print_timestamps(false);
+            state.full(params, &samples).map_err(failed)?;
+
+            let count = state.full_n_segments().map_err(failed)?;
+            let mut segments = Vec::with_capacity(count.max(0) as usize);
+            for i in 0..count {
+                let text = state.full_get_segment_text(i).map_err(failed)?;
+                // Whisper timestamps are in centiseconds.
+                let start = state.full_get_segment_t0(i).map_err(failed)?;
+                let end = state.full_get_segment_t1(i).map_err(failed)?;
+                segments.push(TranscriptionSegment {
+                    text: text.trim().to_string(),
+                    start_secs: start as f32 / 100.0,
+                    end_secs: end as f32 / 100.0,
+                    confidence: 1.0,
+                    speaker: None,
+                });
+            }
+            Ok::<_, VoiceError>(segments)
+        })
+        .await
+        .map_err(|e| VoiceError::TranscriptionFailed {
+            message: format!("Whisper worker failed: {}", e),
+        })??;
+
+        let text = segments
+            .iter()
+            .map(|s| s.text.as_str())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(TranscriptionResult {
+            text,
+            confidence: 1.0,
+            language: Some(self.language.clone()),
+            segments,
+            duration_secs,
         })
     }
 
@@ -324,6 +524,51 @@ mod tests {
         assert_eq!(provider.base_url, "https://custom.api.com/v1");
     }
 
+    #[test]
+    fn test_parse_verbose_json_segments() {
+        let json = serde_json::json!({
+            "text": "Hello there. Next item.",
+            "language": "english",
+            "duration": 4.2,
+            "segments": [
+                {"start": 0.0, "end": 1.5, "text": " Hello there.", "avg_logprob": -0.1},
+                {"start": 1.5, "end": 4.2, "text": " Next item."}
+            ]
+        });
+        let result = parse_verbose_json(&json);
+        assert_eq!(result.text, "Hello there. Next item.");
+        assert_eq!(result.segments.len(), 2);
+        assert_eq!(result.segments[0].text, "Hello there.");
+        assert!((result.segments[0].confidence - 0.905).abs() < 0.01);
+        assert!((result.segments[1].end_secs - 4.2).abs() < f32::EPSILON);
+        assert!((result.segments[1].confidence - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_whisper_config_model_path() {
+        let config = WhisperConfig {
+            model: "small".into(),
+            ..Default::default()
+        };
+        assert!(
+            config
+                .resolve_model_path()
+                .ends_with("models/ggml-small.bin")
+        );
+
+        let config = WhisperConfig {
+            model_path: Some(PathBuf::from("/opt/whisper/ggml-large-v3.bin")),
+            threads: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.resolve_model_path(),
+            PathBuf::from("/opt/whisper/ggml-large-v3.bin")
+        );
+        assert_eq!(config.thread_count(), 3);
+        assert!(WhisperConfig::default().thread_count() >= 1);
+    }
+
     #[test]
     fn test_audio_to_wav_encoding() {
         use super::super::audio_io::audio_convert;
@@ -4,14 +4,26 @@
 //! Gateway, Dashboard) share to start/stop voice and meeting sessions.
 
 use super::meeting_session::{MeetingRecordingSession, MeetingResult, MeetingStatus};
-use super::session::VoiceCommandSession;
+use super::session::{VoiceCommandSession, VoiceConfirmation, VoiceTurn};
 use crate::config::{AgentConfig, MeetingConfig};
 use crate::error::VoiceError;
+use crate::safety::{ActionRequest, ApprovalDecision};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, broadcast, mpsc};
 use tracing::info;
 
+/// A risky action denied because it was not confirmed by voice.
+#[derive(Debug, Clone)]
+pub struct VoiceDenial {
+    /// Tool the action would have run.
+    pub tool_name: String,
+    /// Description that was read out.
+    pub description: String,
+    /// What the user said instead, or `None` after silence.
+    pub heard: Option<String>,
+}
+
 /// Shared state container for voice command and meeting recording toggles.
 ///
 /// Wrapped in `Arc` and passed to REPL, TUI, Gateway, and Dashboard
@@ -19,25 +31,24 @@ use tracing::info;
 pub struct ToggleState {
     voice_session: Mutex<Option<VoiceCommandSession>>,
     meeting_session: Mutex<Option<MeetingRecordingSession>>,
+    voice_denials: broadcast::Sender<VoiceDenial>,
 }
 
 impl ToggleState {
     /// Create a new toggle state (no sessions active).
     pub fn new() -> Arc<Self> {
-        Arc::new(Self {
-            voice_session: Mutex::new(None),
-            meeting_session: Mutex::new(None),
-        })
+        Arc::new(Self::default())
     }
 
     // ── Voice Commands ──────────────────────────────────────────────
 
-    /// Start the voice command session.
+    /// Start the voice command session. Each spoken request arrives on
+    /// `turns` for the caller to run through its agent.
     pub async fn voice_start(
         &self,
         config: AgentConfig,
         workspace: PathBuf,
-        on_transcription: Arc<dyn Fn(String) + Send + Sync>,
+        turns: mpsc::Sender<VoiceTurn>,
     ) -> Result<(), VoiceError> {
         let mut guard = self.voice_session.lock().await;
         if guard.as_ref().is_some_and(|s| s.is_active()) {
@@ -46,7 +57,7 @@ impl ToggleState {
             });
         }
 
-        let session = VoiceCommandSession::start(config, workspace, on_transcription).await?;
+        let session = VoiceCommandSession::start(config, workspace, turns).await?;
         *guard = Some(session);
         info!("Voice command session toggled ON");
         Ok(())
@@ -73,6 +84,47 @@ impl ToggleState {
         guard.as_ref().is_some_and(|s| s.is_active())
     }
 
+    /// Ask for approval by voice when the action comes from a voice turn.
+    ///
+    /// Returns `None` when no voice turn is in progress, so the caller falls
+    /// back to its usual prompt. Only a configured confirmation phrase
+    /// approves; anything else denies and is published to
+    /// [`subscribe_voice_denials`](Self::subscribe_voice_denials).
+    pub async fn voice_approval(&self, action: &ActionRequest) -> Option<ApprovalDecision> {
+        let control = {
+            let guard = self.voice_session.lock().await;
+            guard.as_ref().filter(|s| s.is_active())?.control()
+        };
+        if !control.turn_in_progress() {
+            return None;
+        }
+
+        let prompt = format!(
+            "Approval needed, {} risk: {}",
+            action.risk_level, action.description
+        );
+        let confirmation = control
+            .confirm(prompt)
+            .await
+            .unwrap_or(VoiceConfirmation::Denied { heard: None });
+        match confirmation {
+            VoiceConfirmation::Confirmed => Some(ApprovalDecision::Approve),
+            VoiceConfirmation::Denied { heard } => {
+                let _ = self.voice_denials.send(VoiceDenial {
+                    tool_name: action.tool_name.clone(),
+                    description: action.description.clone(),
+                    heard,
+                });
+                Some(ApprovalDecision::Deny)
+            }
+        }
+    }
+
+    /// Receive actions denied by [`voice_approval`](Self::voice_approval).
+    pub fn subscribe_voice_denials(&self) -> broadcast::Receiver<VoiceDenial> {
+        self.voice_denials.subscribe()
+    }
+
     // ── Meeting Recording ───────────────────────────────────────────
 
     /// Start a meeting recording session.
@@ -146,6 +198,7 @@ impl Default for ToggleState {
         Self {
             voice_session: Mutex::new(None),
             meeting_session: Mutex::new(None),
+            voice_denials: broadcast::channel(16).0,
         }
     }
 }
@@ -169,6 +222,20 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_voice_approval_without_session() {
+        let state = ToggleState::new();
+        let action = crate::SafetyGuardian::create_action_request(
+            "shell_exec",
+            crate::RiskLevel::Execute,
+            "Run make deploy",
+            crate::safety::ActionDetails::ShellCommand {
+                command: "make deploy".into(),
+            },
+        );
+        assert!(state.voice_approval(&action).await.is_none());
+    }
+
     #[tokio::test]
     async fn test_meeting_stop_without_start() {
         let state = ToggleState::new();
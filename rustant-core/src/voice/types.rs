@@ -117,6 +117,18 @@ pub struct TranscriptionSegment {
     pub end_secs: f32,
     /// Confidence score (0.0 - 1.0).
     pub confidence: f32,
+    /// Speaker label from diarization (e.g., "Speaker 1").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+impl TranscriptionSegment {
+    /// Shift the segment's timestamps by `offset_secs`.
+    pub fn offset(mut self, offset_secs: f32) -> Self {
+        self.start_secs += offset_secs;
+        self.end_secs += offset_secs;
+        self
+    }
 }
 
 /// Result of a speech-to-text transcription.
@@ -299,6 +311,7 @@ mod tests {
                 start_secs: 0.0,
                 end_secs: 0.5,
                 confidence: 0.97,
+                speaker: Some("Speaker 1".to_string()),
             }],
             duration_secs: 1.2,
         };
@@ -308,6 +321,10 @@ mod tests {
         assert!((deserialized.confidence - 0.95).abs() < f32::EPSILON);
         assert_eq!(deserialized.language, Some("en".to_string()));
         assert_eq!(deserialized.segments.len(), 1);
+        assert_eq!(
+            deserialized.segments[0].speaker.as_deref(),
+            Some("Speaker 1")
+        );
     }
 
     #[test]
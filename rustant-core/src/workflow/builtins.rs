@@ -106,6 +106,13 @@ steps:
     tool: file_read
     params:
       path: "{{ inputs.path }}"
+  - id: pending_changes
+    tool: git_diff
+    params:
+      path: "{{ inputs.path }}"
+      summarize: true
+    on_error:
+      action: skip
   - id: analyze
     tool: echo
     params:
@@ -415,9 +422,10 @@ inputs:
     description: Base branch to diff against
 steps:
   - id: fetch_diff
-    tool: shell_exec
+    tool: git_diff
     params:
-      command: "git diff {{ inputs.base }}...{{ inputs.branch }}"
+      rev: "{{ inputs.base }}...{{ inputs.branch }}"
+      summarize: true
     on_error:
       action: fail
   - id: changed_files
@@ -432,16 +440,10 @@ steps:
       command: "git log --oneline {{ inputs.base }}..{{ inputs.branch }}"
     on_error:
       action: skip
-  - id: line_stats
-    tool: shell_exec
-    params:
-      command: "git diff --stat {{ inputs.base }}...{{ inputs.branch }}"
-    on_error:
-      action: skip
   - id: review_summary
     tool: echo
     params:
-      text: "PR review for {{ inputs.branch }} against {{ inputs.base }} complete. Diff, changed files, commits, and stats collected."
+      text: "PR review for {{ inputs.branch }} against {{ inputs.base }} complete. Diff summary, changed files, and commits collected."
 outputs:
   - name: review
     value: "{{ steps.review_summary.output }}"
@@ -657,7 +659,7 @@ inputs:
     description: Description of the task to perform in the app
 steps:
   - id: open_app
-    tool: macos_app_control
+    tool: app_control
     params:
       action: "open"
       app_name: "{{ inputs.app_name }}"
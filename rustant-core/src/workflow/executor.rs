@@ -1,15 +1,18 @@
 //! Workflow executor — runs workflow steps sequentially, gating on approvals,
 //! handling errors, and persisting state for pause/resume.
 
+use crate::attachments::{AttachmentStore, default_root, is_handle, resolve_in};
 use crate::error::WorkflowError;
-use crate::workflow::templates::{TemplateContext, evaluate_condition, render_value};
+use crate::types::Attachment;
+use crate::workflow::templates::{TemplateContext, evaluate_condition, lookup, render_value};
 use crate::workflow::types::{
-    ApprovalDecision, ErrorAction, GateType, WorkflowDefinition, WorkflowState, WorkflowStatus,
+    ApprovalDecision, ErrorAction, GateType, StepOutput, ValueType, WorkflowDefinition,
+    WorkflowState, WorkflowStatus, WorkflowStep,
 };
 use async_trait::async_trait;
-use serde_json::Value;
+use serde_json::{Value, json};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
@@ -71,6 +74,8 @@ pub struct WorkflowExecutor {
     approval_handler: Arc<dyn ApprovalHandler>,
     runs: Arc<Mutex<HashMap<Uuid, WorkflowState>>>,
     state_path: Option<PathBuf>,
+    /// Files published by `artifact` step outputs, kept for later steps.
+    attachments: Arc<Mutex<AttachmentStore>>,
 }
 
 impl WorkflowExecutor {
@@ -84,9 +89,17 @@ impl WorkflowExecutor {
             approval_handler,
             runs: Arc::new(Mutex::new(HashMap::new())),
             state_path,
+            attachments: Arc::new(Mutex::new(AttachmentStore::new(Uuid::new_v4()))),
         }
     }
 
+    /// Keep artifact outputs in an attachment store under `root` instead of
+    /// the default attachment directory.
+    pub fn with_attachment_root(mut self, root: &Path) -> Self {
+        self.attachments = Arc::new(Mutex::new(AttachmentStore::with_root(root, Uuid::new_v4())));
+        self
+    }
+
     /// Start a new workflow run.
     pub async fn start(
         &self,
@@ -147,7 +160,8 @@ impl WorkflowExecutor {
             ApprovalDecision::Approved => {
                 // Execute the current gated step
                 let step = &workflow.steps[state.current_step_index];
-                let ctx = TemplateContext::new(state.inputs.clone(), state.step_outputs.clone());
+                let ctx = TemplateContext::new(state.inputs.clone(), state.step_outputs.clone())
+                    .with_named_outputs(state.named_outputs.clone());
 
                 let rendered_params =
                     render_value(&serde_json::to_value(&step.params).unwrap(), &ctx).map_err(
@@ -166,7 +180,12 @@ impl WorkflowExecutor {
                         message: e,
                     })?;
 
-                state.step_outputs.insert(step.id.clone(), output);
+                self.record_step_output(step, output, &mut state)
+                    .await
+                    .map_err(|message| WorkflowError::StepFailed {
+                        step: step.id.clone(),
+                        message,
+                    })?;
                 state.current_step_index += 1;
                 state.status = WorkflowStatus::Running;
                 state.updated_at = chrono::Utc::now();
@@ -221,7 +240,8 @@ impl WorkflowExecutor {
     ) -> Result<WorkflowState, WorkflowError> {
         while state.current_step_index < workflow.steps.len() {
             let step = &workflow.steps[state.current_step_index];
-            let ctx = TemplateContext::new(state.inputs.clone(), state.step_outputs.clone());
+            let ctx = TemplateContext::new(state.inputs.clone(), state.step_outputs.clone())
+                .with_named_outputs(state.named_outputs.clone());
 
             // Check condition
             if let Some(ref condition) = step.condition {
@@ -274,7 +294,12 @@ impl WorkflowExecutor {
 
             match result {
                 Ok(output) => {
-                    state.step_outputs.insert(step.id.clone(), output);
+                    if let Err(message) = self.record_step_output(step, output, &mut state).await {
+                        state.status = WorkflowStatus::Failed;
+                        state.error = Some(format!("Step '{}' failed: {}", step.id, message));
+                        state.updated_at = chrono::Utc::now();
+                        return Ok(state);
+                    }
                     state.current_step_index += 1;
                     state.updated_at = chrono::Utc::now();
                 }
@@ -294,7 +319,8 @@ impl WorkflowExecutor {
                             let ctx2 = TemplateContext::new(
                                 state.inputs.clone(),
                                 state.step_outputs.clone(),
-                            );
+                            )
+                            .with_named_outputs(state.named_outputs.clone());
                             let params_value2 = serde_json::to_value(&step.params)
                                 .unwrap_or(Value::Object(Default::default()));
                             let rendered2 = render_value(&params_value2, &ctx2).map_err(|e| {
@@ -305,7 +331,15 @@ impl WorkflowExecutor {
                             })?;
                             match self.tool_executor.execute_tool(&step.tool, rendered2).await {
                                 Ok(output) => {
-                                    state.step_outputs.insert(step.id.clone(), output);
+                                    if let Err(message) =
+                                        self.record_step_output(step, output, &mut state).await
+                                    {
+                                        state.status = WorkflowStatus::Failed;
+                                        state.error =
+                                            Some(format!("Step '{}' failed: {}", step.id, message));
+                                        state.updated_at = chrono::Utc::now();
+                                        return Ok(state);
+                                    }
                                     state.current_step_index += 1;
                                     state.updated_at = chrono::Utc::now();
                                     last_err = String::new();
@@ -341,6 +375,94 @@ impl WorkflowExecutor {
         Ok(state)
     }
 
+    /// Record a step's result and publish its declared outputs, checking
+    /// each against its type. `artifact` outputs are copied into the
+    /// attachment store and published as a handle.
+    async fn record_step_output(
+        &self,
+        step: &WorkflowStep,
+        output: Value,
+        state: &mut WorkflowState,
+    ) -> Result<(), String> {
+        let mut named = HashMap::new();
+        let only = step.outputs.len() == 1;
+        for declared in &step.outputs {
+            let raw = match &declared.from {
+                Some(from) => lookup(&output, &from.split('.').collect::<Vec<_>>()).cloned(),
+                None => output
+                    .get(&declared.name)
+                    .cloned()
+                    .or_else(|| only.then(|| output.clone())),
+            }
+            .ok_or_else(|| {
+                format!(
+                    "output '{}' not found in the result of {}",
+                    declared.name, step.tool
+                )
+            })?;
+            let value_type = declared.value_type().unwrap_or(ValueType::Json);
+            let mut value = value_type
+                .coerce(raw)
+                .map_err(|e| format!("output '{}' {}", declared.name, e))?;
+            if value_type == ValueType::Artifact {
+                value = self.store_artifact(step, declared, value).await?;
+            }
+            named.insert(declared.name.clone(), value);
+        }
+        state.step_outputs.insert(step.id.clone(), output);
+        if !named.is_empty() {
+            state.named_outputs.insert(step.id.clone(), named);
+        }
+        Ok(())
+    }
+
+    /// Put the file behind an `artifact` output into the attachment store,
+    /// or look up the attachment it already names.
+    async fn store_artifact(
+        &self,
+        step: &WorkflowStep,
+        declared: &StepOutput,
+        value: Value,
+    ) -> Result<Value, String> {
+        let location = match &value {
+            Value::String(s) => s.clone(),
+            other => other["path"].as_str().unwrap_or_default().to_string(),
+        };
+        let failed = |e: crate::attachments::AttachmentError| {
+            format!("output '{}' could not be stored: {}", declared.name, e)
+        };
+        let mut store = self.attachments.lock().await;
+        let (handle, path) = if is_handle(&location) {
+            let root = store
+                .dir()
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(default_root);
+            resolve_in(&root, &location).map_err(failed)?
+        } else {
+            let path = PathBuf::from(&location);
+            let description = if declared.description.is_empty() {
+                format!("{} from workflow step '{}'", declared.name, step.id)
+            } else {
+                declared.description.clone()
+            };
+            let handle = store
+                .store(Attachment::file(mime_type_for(&path), description, &path))
+                .map_err(failed)?;
+            (handle, path)
+        };
+        let title = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| handle.id.clone());
+        Ok(json!({
+            "handle": handle.id,
+            "path": path.display().to_string(),
+            "mime_type": handle.mime_type,
+            "title": title,
+        }))
+    }
+
     /// Persist workflow state to disk.
     async fn persist_state(
         &self,
@@ -382,6 +504,32 @@ impl WorkflowExecutor {
     }
 }
 
+/// MIME type of an artifact file, from its extension.
+fn mime_type_for(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "html" | "htm" => "text/html",
+        "md" => "text/markdown",
+        "txt" | "log" => "text/plain",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -754,4 +902,104 @@ steps:
         assert_eq!(loaded.status, WorkflowStatus::Completed);
         assert_eq!(loaded.workflow_name, "test_workflow");
     }
+
+    /// Returns queued responses in order and records the arguments of each call.
+    struct RecordingToolExecutor {
+        responses: Mutex<Vec<Value>>,
+        calls: Mutex<Vec<(String, Value)>>,
+    }
+
+    #[async_trait]
+    impl ToolExecutor for RecordingToolExecutor {
+        async fn execute_tool(&self, tool_name: &str, args: Value) -> Result<Value, String> {
+            self.calls.lock().await.push((tool_name.to_string(), args));
+            let mut responses = self.responses.lock().await;
+            Ok(if responses.is_empty() {
+                Value::Null
+            } else {
+                responses.remove(0)
+            })
+        }
+    }
+
+    fn typed_outputs_yaml() -> &'static str {
+        r#"
+name: report_and_mail
+description: Build a PDF and mail it
+steps:
+  - id: build
+    tool: pdf_generate
+    outputs:
+      - name: report
+        type: artifact
+        from: file.path
+      - name: pages
+        type: number
+  - id: mail
+    tool: macos_mail
+    params:
+      attachment: "${steps.build.outputs.report}"
+      path: "${steps.build.outputs.report.path}"
+      pages: "{{ steps.build.outputs.pages }}"
+      subject: "Report (${steps.build.outputs.pages} pages)"
+"#
+    }
+
+    #[tokio::test]
+    async fn test_executor_passes_typed_outputs_and_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let pdf = dir.path().join("report.pdf");
+        std::fs::write(&pdf, b"%PDF-1.4").unwrap();
+        let tools = Arc::new(RecordingToolExecutor {
+            responses: Mutex::new(vec![
+                json!({"file": {"path": pdf.display().to_string()}, "pages": "4"}),
+                json!("sent"),
+            ]),
+            calls: Mutex::new(Vec::new()),
+        });
+        let executor = WorkflowExecutor::new(tools.clone(), Arc::new(AutoApproveHandler), None)
+            .with_attachment_root(&dir.path().join("attachments"));
+        let wf = parse_workflow(typed_outputs_yaml()).unwrap();
+        let state = executor.start(&wf, HashMap::new()).await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Completed, "{:?}", state.error);
+
+        let report = &state.named_outputs["build"]["report"];
+        let handle = report["handle"].as_str().unwrap();
+        assert!(crate::attachments::is_handle(handle));
+        assert_eq!(report["mime_type"], "application/pdf");
+        assert_eq!(state.named_outputs["build"]["pages"], json!(4));
+
+        let calls = tools.calls.lock().await;
+        let (tool, args) = &calls[1];
+        assert_eq!(tool, "macos_mail");
+        assert_eq!(args["attachment"], handle);
+        assert_eq!(args["path"], pdf.display().to_string());
+        assert_eq!(args["pages"], json!(4));
+        assert_eq!(args["subject"], "Report (4 pages)");
+    }
+
+    #[tokio::test]
+    async fn test_executor_fails_step_on_output_type_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let pdf = dir.path().join("report.pdf");
+        std::fs::write(&pdf, b"%PDF-1.4").unwrap();
+        let tools = Arc::new(RecordingToolExecutor {
+            responses: Mutex::new(vec![json!({
+                "file": {"path": pdf.display().to_string()},
+                "pages": "Four pages, roughly"
+            })]),
+            calls: Mutex::new(Vec::new()),
+        });
+        let executor = WorkflowExecutor::new(tools.clone(), Arc::new(AutoApproveHandler), None)
+            .with_attachment_root(&dir.path().join("attachments"));
+        let wf = parse_workflow(typed_outputs_yaml()).unwrap();
+        let state = executor.start(&wf, HashMap::new()).await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Failed);
+        let error = state.error.unwrap();
+        assert!(
+            error.contains("output 'pages' expected number, got 'Four pages, roughly'"),
+            "{error}"
+        );
+        assert_eq!(tools.calls.lock().await.len(), 1);
+    }
 }
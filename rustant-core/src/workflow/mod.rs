@@ -1,7 +1,8 @@
 //! Workflow Engine for Rustant.
 //!
 //! Provides a declarative YAML DSL for defining multi-step workflows with
-//! typed parameters, approval gates, conditional execution, and error handling.
+//! typed inputs and step outputs, approval gates, conditional execution, and
+//! error handling.
 
 pub mod builtins;
 pub mod executor;
@@ -13,8 +14,8 @@ pub use builtins::{all_builtins, get_builtin, list_builtin_names};
 pub use executor::{
     ApprovalHandler, AutoApproveHandler, AutoDenyHandler, ToolExecutor, WorkflowExecutor,
 };
-pub use parser::{parse_workflow, validate_workflow};
+pub use parser::{parse_inputs, parse_workflow, resolve_step_tools, validate_workflow};
 pub use types::{
-    ApprovalDecision, ErrorAction, GateConfig, GateType, WorkflowDefinition, WorkflowInput,
-    WorkflowOutput, WorkflowState, WorkflowStatus, WorkflowStep,
+    ApprovalDecision, ErrorAction, GateConfig, GateType, StepOutput, ValueType, WorkflowDefinition,
+    WorkflowInput, WorkflowOutput, WorkflowState, WorkflowStatus, WorkflowStep,
 };
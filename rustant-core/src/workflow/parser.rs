@@ -1,15 +1,44 @@
 //! YAML DSL parser and validator for workflow definitions.
 
 use crate::error::WorkflowError;
-use crate::workflow::templates::extract_references;
-use crate::workflow::types::WorkflowDefinition;
-use std::collections::HashSet;
+use crate::tool_aliases::AliasTable;
+use crate::workflow::templates::{extract_expressions, whole_expression};
+use crate::workflow::types::{ValueType, WorkflowDefinition, WorkflowStep};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
 
-/// Parse a workflow definition from a YAML string.
+/// Parse a workflow definition from a YAML string. Steps that call a renamed
+/// tool by its old name are rewritten to the new name with a warning.
 pub fn parse_workflow(yaml: &str) -> Result<WorkflowDefinition, WorkflowError> {
-    serde_yaml::from_str::<WorkflowDefinition>(yaml).map_err(|e| WorkflowError::ParseError {
-        message: e.to_string(),
-    })
+    let mut workflow = serde_yaml::from_str::<WorkflowDefinition>(yaml).map_err(|e| {
+        WorkflowError::ParseError {
+            message: e.to_string(),
+        }
+    })?;
+    resolve_step_tools(&mut workflow, &AliasTable::builtin())?;
+    Ok(workflow)
+}
+
+/// Rewrite steps that name a tool alias to call the canonical tool, warning
+/// about each. Steps naming a removed alias fail validation.
+pub fn resolve_step_tools(
+    workflow: &mut WorkflowDefinition,
+    aliases: &AliasTable,
+) -> Result<(), WorkflowError> {
+    for step in &mut workflow.steps {
+        let (canonical, warning) =
+            aliases
+                .check_reference(&step.tool)
+                .map_err(|e| WorkflowError::ValidationFailed {
+                    message: format!("Step '{}': {}", step.id, e),
+                })?;
+        if let Some(warning) = warning {
+            warn!(workflow = %workflow.name, step = %step.id, "{}", warning);
+            step.tool = canonical;
+        }
+    }
+    Ok(())
 }
 
 /// Validate a parsed workflow definition for structural correctness.
@@ -17,7 +46,12 @@ pub fn parse_workflow(yaml: &str) -> Result<WorkflowDefinition, WorkflowError> {
 /// Checks:
 /// - At least one step exists
 /// - No duplicate step IDs
-/// - All template step references point to earlier steps
+/// - Input, step output and workflow output types are known, and input
+///   defaults match their type
+/// - All template step references point to earlier steps, and references to
+///   named step outputs point to declared outputs
+/// - Typed workflow outputs receive a value of their type
+/// - No step calls a removed tool alias
 pub fn validate_workflow(workflow: &WorkflowDefinition) -> Result<(), WorkflowError> {
     // Must have at least one step
     if workflow.steps.is_empty() {
@@ -26,6 +60,15 @@ pub fn validate_workflow(workflow: &WorkflowDefinition) -> Result<(), WorkflowEr
         });
     }
 
+    let aliases = AliasTable::builtin();
+    for step in &workflow.steps {
+        aliases
+            .check_reference(&step.tool)
+            .map_err(|e| WorkflowError::ValidationFailed {
+                message: format!("Step '{}': {}", step.id, e),
+            })?;
+    }
+
     // Check for duplicate step IDs
     let mut seen_ids = HashSet::new();
     for step in &workflow.steps {
@@ -36,87 +79,243 @@ pub fn validate_workflow(workflow: &WorkflowDefinition) -> Result<(), WorkflowEr
         }
     }
 
-    // Validate that template references point to known steps
-    let step_ids: HashSet<&str> = workflow.steps.iter().map(|s| s.id.as_str()).collect();
-    let input_names: HashSet<&str> = workflow.inputs.iter().map(|i| i.name.as_str()).collect();
+    check_declared_types(workflow)?;
 
+    // Validate that template references point to known steps
     for (idx, step) in workflow.steps.iter().enumerate() {
-        let earlier_steps: HashSet<&str> = workflow.steps[..idx]
-            .iter()
-            .map(|s| s.id.as_str())
-            .collect();
-
-        // Check param templates
-        for value in step.params.values() {
-            if let Some(s) = value.as_str() {
-                check_template_refs(s, &earlier_steps, &input_names, &step.id)?;
-            }
+        let earlier_steps = &workflow.steps[..idx];
+        for expr in step.references() {
+            expression_type(&expr, workflow, earlier_steps, &step.id)?;
         }
+    }
 
-        // Check condition template
-        if let Some(cond) = &step.condition {
-            check_template_refs(cond, &earlier_steps, &input_names, &step.id)?;
+    // Validate output templates
+    for output in &workflow.outputs {
+        for expr in extract_expressions(&output.value) {
+            expression_type(&expr, workflow, &workflow.steps, "output")?;
+        }
+        let expected = output.output_type.as_deref().and_then(ValueType::parse);
+        if let (Some(expected), Some(expr)) = (expected, whole_expression(&output.value))
+            && let Some(actual) = expression_type(&expr, workflow, &workflow.steps, "output")?
+            && !expected.accepts(&actual)
+        {
+            return Err(WorkflowError::ValidationFailed {
+                message: format!(
+                    "Workflow output '{}' expects {} but '{}' is {}",
+                    output.name, expected, expr, actual
+                ),
+            });
         }
+    }
+
+    Ok(())
+}
 
-        // Check gate preview/message templates
-        if let Some(msg) = &step.gate_message {
-            check_template_refs(msg, &earlier_steps, &input_names, &step.id)?;
+/// Check that every declared type is known and every input default matches
+/// its input's type.
+fn check_declared_types(workflow: &WorkflowDefinition) -> Result<(), WorkflowError> {
+    let unknown = |what: String, type_name: &str| WorkflowError::ValidationFailed {
+        message: format!(
+            "{} has unknown type '{}' (use string, number, boolean, path, json, artifact, \
+             or a list such as string[])",
+            what, type_name
+        ),
+    };
+    for input in &workflow.inputs {
+        let value_type = input
+            .value_type()
+            .ok_or_else(|| unknown(format!("Input '{}'", input.name), &input.input_type))?;
+        if let Some(default) = &input.default {
+            value_type
+                .coerce(default.clone())
+                .map_err(|e| WorkflowError::ValidationFailed {
+                    message: format!("Default of input '{}': {}", input.name, e),
+                })?;
         }
-        if let Some(preview) = &step.gate_preview {
-            check_template_refs(preview, &earlier_steps, &input_names, &step.id)?;
+    }
+    for step in &workflow.steps {
+        let mut names = HashSet::new();
+        for output in &step.outputs {
+            if output.value_type().is_none() {
+                return Err(unknown(
+                    format!("Output '{}' of step '{}'", output.name, step.id),
+                    &output.output_type,
+                ));
+            }
+            if !names.insert(&output.name) {
+                return Err(WorkflowError::ValidationFailed {
+                    message: format!("Step '{}' declares output '{}' twice", step.id, output.name),
+                });
+            }
         }
     }
-
-    // Validate output templates
     for output in &workflow.outputs {
-        let all_steps: HashSet<&str> = step_ids.iter().copied().collect();
-        check_template_refs(&output.value, &all_steps, &input_names, "output")?;
+        if let Some(type_name) = &output.output_type
+            && ValueType::parse(type_name).is_none()
+        {
+            return Err(unknown(
+                format!("Workflow output '{}'", output.name),
+                type_name,
+            ));
+        }
     }
-
     Ok(())
 }
 
-/// Check that all template references in a string point to known steps/inputs.
-fn check_template_refs(
-    template: &str,
-    known_steps: &HashSet<&str>,
-    known_inputs: &HashSet<&str>,
+/// Check that a template expression refers to a known input or an earlier
+/// step, and return its type when it is declared.
+fn expression_type(
+    expr: &str,
+    workflow: &WorkflowDefinition,
+    known_steps: &[WorkflowStep],
     context_step: &str,
-) -> Result<(), WorkflowError> {
-    let refs = extract_references(template);
-    for (namespace, name) in refs {
-        match namespace.as_str() {
-            "steps" => {
-                if !known_steps.contains(name.as_str()) {
-                    return Err(WorkflowError::ValidationFailed {
-                        message: format!(
-                            "Step '{}' references unknown step '{}' (steps must reference earlier steps)",
-                            context_step, name
-                        ),
-                    });
-                }
+) -> Result<Option<ValueType>, WorkflowError> {
+    let failed = |message: String| WorkflowError::ValidationFailed { message };
+    let parts: Vec<&str> = expr.split('.').collect();
+    let name = parts.get(1).copied().unwrap_or_default();
+    match parts[0] {
+        "steps" => {
+            let step = known_steps.iter().find(|s| s.id == name).ok_or_else(|| {
+                failed(format!(
+                    "Step '{}' references unknown step '{}' (steps must reference earlier steps)",
+                    context_step, name
+                ))
+            })?;
+            if parts.get(2) != Some(&"outputs") {
+                return Ok(None);
             }
-            "inputs" => {
-                if !known_inputs.contains(name.as_str()) {
-                    return Err(WorkflowError::ValidationFailed {
-                        message: format!(
-                            "Step '{}' references unknown input '{}'",
-                            context_step, name
-                        ),
-                    });
-                }
+            let output_name = parts.get(3).copied().unwrap_or_default();
+            let output = step
+                .outputs
+                .iter()
+                .find(|o| o.name == output_name)
+                .ok_or_else(|| {
+                    failed(format!(
+                        "Step '{}' references undeclared output '{}' of step '{}'",
+                        context_step, output_name, step.id
+                    ))
+                })?;
+            let value_type = output.value_type().unwrap_or(ValueType::Json);
+            field_type(value_type, &parts[4..]).map(Some).map_err(|e| {
+                failed(format!(
+                    "Step '{}' references '{}': {}",
+                    context_step, expr, e
+                ))
+            })
+        }
+        "inputs" => {
+            let input = workflow
+                .inputs
+                .iter()
+                .find(|i| i.name == name)
+                .ok_or_else(|| {
+                    failed(format!(
+                        "Step '{}' references unknown input '{}'",
+                        context_step, name
+                    ))
+                })?;
+            let value_type = input.value_type().unwrap_or(ValueType::Json);
+            field_type(value_type, &parts[2..]).map(Some).map_err(|e| {
+                failed(format!(
+                    "Step '{}' references '{}': {}",
+                    context_step, expr, e
+                ))
+            })
+        }
+        namespace => Err(failed(format!(
+            "Step '{}' has unknown template namespace '{}'",
+            context_step, namespace
+        ))),
+    }
+}
+
+/// Type of a field reached by following `fields` into a value of `value_type`.
+fn field_type(value_type: ValueType, fields: &[&str]) -> Result<ValueType, String> {
+    let Some((field, rest)) = fields.split_first() else {
+        return Ok(value_type);
+    };
+    match (&value_type, *field) {
+        (ValueType::Json, _) => Ok(ValueType::Json),
+        (ValueType::Artifact, "path") if rest.is_empty() => Ok(ValueType::Path),
+        (ValueType::Artifact, "handle" | "mime_type" | "title") if rest.is_empty() => {
+            Ok(ValueType::String)
+        }
+        (ValueType::List(inner), index) if index.parse::<usize>().is_ok() => {
+            field_type((**inner).clone(), rest)
+        }
+        _ => Err(format!("{} values have no field '{}'", value_type, field)),
+    }
+}
+
+/// Parse `key=value` workflow inputs against the declared input types and
+/// fill in defaults.
+///
+/// Unknown input names, missing required inputs and values of the wrong type
+/// are rejected with the expected type named.
+pub fn parse_inputs(
+    workflow: &WorkflowDefinition,
+    pairs: &[String],
+) -> Result<HashMap<String, Value>, WorkflowError> {
+    let mut inputs = HashMap::new();
+    for pair in pairs {
+        let (key, raw) = pair
+            .split_once('=')
+            .ok_or_else(|| WorkflowError::InvalidInput {
+                name: pair.clone(),
+                message: "expected key=value".to_string(),
+            })?;
+        let key = key.trim();
+        let input = workflow
+            .inputs
+            .iter()
+            .find(|i| i.name == key)
+            .ok_or_else(|| WorkflowError::InvalidInput {
+                name: key.to_string(),
+                message: format!(
+                    "'{}' takes no such input; known inputs: {}",
+                    workflow.name,
+                    workflow
+                        .inputs
+                        .iter()
+                        .map(|i| i.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            })?;
+        let value_type = input.value_type().unwrap_or(ValueType::String);
+        let value = value_type
+            .coerce(Value::String(raw.to_string()))
+            .map_err(|message| WorkflowError::InvalidInput {
+                name: key.to_string(),
+                message,
+            })?;
+        inputs.insert(key.to_string(), value);
+    }
+    for input in &workflow.inputs {
+        if inputs.contains_key(&input.name) {
+            continue;
+        }
+        match &input.default {
+            Some(default) => {
+                let value_type = input.value_type().unwrap_or(ValueType::Json);
+                let value = value_type.coerce(default.clone()).map_err(|message| {
+                    WorkflowError::InvalidInput {
+                        name: input.name.clone(),
+                        message,
+                    }
+                })?;
+                inputs.insert(input.name.clone(), value);
             }
-            _ => {
-                return Err(WorkflowError::ValidationFailed {
-                    message: format!(
-                        "Step '{}' has unknown template namespace '{}'",
-                        context_step, namespace
-                    ),
+            None if !input.optional => {
+                return Err(WorkflowError::InvalidInput {
+                    name: input.name.clone(),
+                    message: format!("required {} input is missing", input.input_type),
                 });
             }
+            None => {}
         }
     }
-    Ok(())
+    Ok(inputs)
 }
 
 #[cfg(test)]
@@ -141,6 +340,34 @@ steps:
         assert_eq!(wf.steps[0].tool, "echo");
     }
 
+    #[test]
+    fn test_steps_calling_renamed_tools_are_rewritten() {
+        let yaml = r#"
+name: apps
+description: Uses an old tool name
+steps:
+  - id: list
+    tool: old_tool
+"#;
+        let mut wf = parse_workflow(yaml).unwrap();
+        let mut aliases = AliasTable::new("1.0.0");
+        aliases.insert(
+            crate::tool_aliases::ToolAlias::new("old_tool", "new_tool", "1.0.0")
+                .removed_in("2.0.0"),
+        );
+        resolve_step_tools(&mut wf, &aliases).unwrap();
+        assert_eq!(wf.steps[0].tool, "new_tool");
+
+        let mut wf = parse_workflow(yaml).unwrap();
+        let mut removed = AliasTable::new("2.0.0");
+        removed.insert(aliases.get("old_tool").unwrap().clone());
+        let err = resolve_step_tools(&mut wf, &removed).unwrap_err();
+        assert!(err.to_string().contains("use 'new_tool' instead"));
+
+        let wf = parse_workflow(&yaml.replace("old_tool", "macos_app_control")).unwrap();
+        assert_eq!(wf.steps[0].tool, "app_control");
+    }
+
     #[test]
     fn test_parse_workflow_with_inputs() {
         let yaml = r#"
@@ -338,4 +565,121 @@ outputs:
         let result = validate_workflow(&wf);
         assert!(result.is_ok());
     }
+
+    const TYPED: &str = r#"
+name: typed
+description: Typed dataflow
+inputs:
+  - name: title
+    type: string
+  - name: pages
+    type: number
+    optional: true
+    default: 3
+steps:
+  - id: build
+    tool: pdf_generate
+    params:
+      title: "${inputs.title}"
+      pages: "${inputs.pages}"
+    outputs:
+      - name: artifact_path
+        type: artifact
+        from: path
+      - name: stats
+        type: json
+  - id: mail
+    tool: macos_mail
+    params:
+      action: send
+      attachment: "${steps.build.outputs.artifact_path}"
+      body:
+        text: "Pages: ${steps.build.outputs.stats.pages}"
+outputs:
+  - name: report
+    type: path
+    value: "${steps.build.outputs.artifact_path.path}"
+"#;
+
+    #[test]
+    fn test_validate_typed_dataflow_passes() {
+        let wf = parse_workflow(TYPED).unwrap();
+        validate_workflow(&wf).unwrap();
+        assert_eq!(
+            wf.steps[1].references(),
+            vec![
+                "steps.build.outputs.artifact_path",
+                "steps.build.outputs.stats.pages"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_undeclared_output_reference() {
+        let wf = parse_workflow(&TYPED.replace(
+            "outputs.artifact_path}\"\n      body",
+            "outputs.pdf}\"\n      body",
+        ))
+        .unwrap();
+        let err = validate_workflow(&wf).unwrap_err().to_string();
+        assert!(
+            err.contains("undeclared output 'pdf' of step 'build'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_validate_output_type_mismatch() {
+        let wf = parse_workflow(&TYPED.replace("type: path", "type: number")).unwrap();
+        let err = validate_workflow(&wf).unwrap_err().to_string();
+        assert!(err.contains("expects number but"), "{err}");
+        assert!(err.contains("is path"), "{err}");
+
+        let wf = parse_workflow(&TYPED.replace(
+            "${steps.build.outputs.artifact_path.path}",
+            "${steps.build.outputs.artifact_path.size}",
+        ))
+        .unwrap();
+        let err = validate_workflow(&wf).unwrap_err().to_string();
+        assert!(
+            err.contains("artifact values have no field 'size'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_validate_unknown_type_and_bad_default() {
+        let wf = parse_workflow(&TYPED.replace("type: json", "type: blob")).unwrap();
+        let err = validate_workflow(&wf).unwrap_err().to_string();
+        assert!(err.contains("unknown type 'blob'"), "{err}");
+
+        let wf = parse_workflow(&TYPED.replace("default: 3", "default: many")).unwrap();
+        let err = validate_workflow(&wf).unwrap_err().to_string();
+        assert!(
+            err.contains("Default of input 'pages': expected number"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_parse_inputs_types_and_defaults() {
+        let wf = parse_workflow(TYPED).unwrap();
+        let inputs = parse_inputs(&wf, &["title=Q3 report".to_string()]).unwrap();
+        assert_eq!(inputs["title"], "Q3 report");
+        assert_eq!(inputs["pages"], 3);
+
+        let inputs = parse_inputs(&wf, &["title=x".into(), "pages=12".into()]).unwrap();
+        assert_eq!(inputs["pages"], 12);
+
+        let err = parse_inputs(&wf, &["title=x".into(), "pages=twelve".into()]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid workflow input 'pages': expected number, got 'twelve'"
+        );
+        let err = parse_inputs(&wf, &[]).unwrap_err();
+        assert!(err.to_string().contains("required string input is missing"));
+        let err = parse_inputs(&wf, &["title=x".into(), "colour=red".into()]).unwrap_err();
+        assert!(err.to_string().contains("known inputs: title, pages"));
+        assert!(parse_inputs(&wf, &["title".into()]).is_err());
+    }
 }
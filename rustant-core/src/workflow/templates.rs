@@ -1,16 +1,23 @@
 //! Template expression engine for workflow parameter substitution.
 //!
-//! Supports `{{ inputs.name }}` and `{{ steps.step_id.output }}` style
-//! expressions, and simple condition evaluation for conditional steps.
+//! Supports `{{ inputs.name }}`, `{{ steps.step_id.output }}` and
+//! `{{ steps.step_id.outputs.name }}` style expressions, written either as
+//! `{{ ... }}` or `${...}`, and simple condition evaluation for conditional
+//! steps. `${...}` only counts as a template when it names `inputs` or
+//! `steps`, so shell variables in commands pass through untouched.
 
+use crate::attachments::is_handle;
 use crate::error::WorkflowError;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::ops::Range;
 
 /// Context for template rendering, containing available variable values.
 pub struct TemplateContext {
     pub inputs: HashMap<String, Value>,
     pub step_outputs: HashMap<String, Value>,
+    /// Declared outputs of each completed step, by step ID and output name.
+    pub named_outputs: HashMap<String, HashMap<String, Value>>,
 }
 
 impl TemplateContext {
@@ -18,39 +25,52 @@ impl TemplateContext {
         Self {
             inputs,
             step_outputs,
+            named_outputs: HashMap::new(),
         }
     }
+
+    /// Make declared step outputs available as `steps.<id>.outputs.<name>`.
+    pub fn with_named_outputs(
+        mut self,
+        named_outputs: HashMap<String, HashMap<String, Value>>,
+    ) -> Self {
+        self.named_outputs = named_outputs;
+        self
+    }
 }
 
-/// Render template expressions in a string value, replacing `{{ ... }}` patterns.
+/// Render template expressions in a string value, replacing `{{ ... }}` and
+/// `${...}` patterns.
 pub fn render_string(template: &str, ctx: &TemplateContext) -> Result<String, WorkflowError> {
     let mut result = String::new();
-    let mut rest = template;
-
-    while let Some(start) = rest.find("{{") {
-        result.push_str(&rest[..start]);
-        let after_open = &rest[start + 2..];
-        let end = after_open
-            .find("}}")
-            .ok_or_else(|| WorkflowError::TemplateError {
-                message: format!("Unclosed template expression in: {}", template),
-            })?;
-        let expr = after_open[..end].trim();
+    let mut last = 0;
+    for (range, expr) in scan(template)? {
+        result.push_str(&template[last..range.start]);
         let value = resolve_expression(expr, ctx)?;
         result.push_str(&value_to_string(&value));
-        rest = &after_open[end + 2..];
+        last = range.end;
     }
-    result.push_str(rest);
+    result.push_str(&template[last..]);
 
     Ok(result)
 }
 
 /// Render template expressions within a JSON Value, recursively processing
 /// strings, objects, and arrays.
+///
+/// A string that is nothing but a reference to a declared step output is
+/// replaced by the output's value, keeping its type, so a `number` output
+/// arrives as a number and a `json` output as an object.
 pub fn render_value(value: &Value, ctx: &TemplateContext) -> Result<Value, WorkflowError> {
     match value {
         Value::String(s) => {
-            if s.contains("{{") {
+            if let Some(expr) = whole_expression(s).filter(|e| is_named_output(e)) {
+                let value = resolve_expression(&expr, ctx)?;
+                Ok(match artifact_handle(&value) {
+                    Some(handle) => Value::String(handle.to_string()),
+                    None => value,
+                })
+            } else if s.contains("{{") || s.contains("${") {
                 let rendered = render_string(s, ctx)?;
                 Ok(Value::String(rendered))
             } else {
@@ -96,26 +116,49 @@ pub fn evaluate_condition(condition: &str, ctx: &TemplateContext) -> Result<bool
     Ok(!trimmed.is_empty() && trimmed != "false" && trimmed != "0")
 }
 
-/// Resolve a dotted expression like `inputs.path` or `steps.fetch_pr.output`.
+/// Resolve a dotted expression like `inputs.path`, `steps.fetch_pr.output`
+/// or `steps.build.outputs.artifact_path`.
 fn resolve_expression(expr: &str, ctx: &TemplateContext) -> Result<Value, WorkflowError> {
-    let parts: Vec<&str> = expr.splitn(3, '.').collect();
+    let parts: Vec<&str> = expr.split('.').collect();
 
     match parts.first() {
         Some(&"inputs") => {
             let key = parts.get(1).ok_or_else(|| WorkflowError::TemplateError {
                 message: format!("Invalid input reference: {}", expr),
             })?;
-            ctx.inputs
+            let value = ctx
+                .inputs
                 .get(*key)
-                .cloned()
                 .ok_or_else(|| WorkflowError::TemplateError {
                     message: format!("Input '{}' not found", key),
+                })?;
+            lookup(value, &parts[2..])
+                .cloned()
+                .ok_or_else(|| WorkflowError::TemplateError {
+                    message: format!("'{}' not found", expr),
                 })
         }
         Some(&"steps") => {
             let step_id = parts.get(1).ok_or_else(|| WorkflowError::TemplateError {
                 message: format!("Invalid step reference: {}", expr),
             })?;
+            if parts.get(2) == Some(&"outputs") {
+                let name = parts.get(3).ok_or_else(|| WorkflowError::TemplateError {
+                    message: format!("Invalid step output reference: {}", expr),
+                })?;
+                let value = ctx
+                    .named_outputs
+                    .get(*step_id)
+                    .and_then(|outputs| outputs.get(*name))
+                    .ok_or_else(|| WorkflowError::TemplateError {
+                        message: format!("Output '{}' of step '{}' not found", name, step_id),
+                    })?;
+                return lookup(value, &parts[4..]).cloned().ok_or_else(|| {
+                    WorkflowError::TemplateError {
+                        message: format!("'{}' not found", expr),
+                    }
+                });
+            }
             // Accept both `steps.id.output` and just `steps.id`
             ctx.step_outputs
                 .get(*step_id)
@@ -130,8 +173,25 @@ fn resolve_expression(expr: &str, ctx: &TemplateContext) -> Result<Value, Workfl
     }
 }
 
+/// Follow object keys and array indices into a JSON value.
+pub(crate) fn lookup<'a>(value: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, key| match value {
+        Value::Object(map) => map.get(*key),
+        Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// The attachment handle of an `artifact` output value.
+pub(crate) fn artifact_handle(value: &Value) -> Option<&str> {
+    value["handle"].as_str().filter(|h| is_handle(h))
+}
+
 /// Convert a JSON Value to its string representation for template insertion.
 fn value_to_string(value: &Value) -> String {
+    if let Some(handle) = artifact_handle(value) {
+        return handle.to_string();
+    }
     match value {
         Value::String(s) => s.clone(),
         Value::Null => String::new(),
@@ -139,27 +199,79 @@ fn value_to_string(value: &Value) -> String {
     }
 }
 
-/// Extract all template variable references from a string.
-/// Returns pairs like `("inputs", "path")` or `("steps", "fetch_pr")`.
-pub fn extract_references(template: &str) -> Vec<(String, String)> {
-    let mut refs = Vec::new();
-    let mut rest = template;
+/// Whether an expression names a declared step output.
+fn is_named_output(expr: &str) -> bool {
+    let parts: Vec<&str> = expr.splitn(4, '.').collect();
+    parts.len() == 4 && parts[0] == "steps" && parts[2] == "outputs"
+}
 
-    while let Some(start) = rest.find("{{") {
-        let after_open = &rest[start + 2..];
-        if let Some(end) = after_open.find("}}") {
+/// Find the template expressions in a string, with their byte ranges.
+fn scan(template: &str) -> Result<Vec<(Range<usize>, &str)>, WorkflowError> {
+    let mut found = Vec::new();
+    let mut pos = 0;
+    while pos < template.len() {
+        let rest = &template[pos..];
+        let Some(offset) = rest.find(['{', '$']) else {
+            break;
+        };
+        let start = pos + offset;
+        let rest = &template[start..];
+        if let Some(after_open) = rest.strip_prefix("{{") {
+            let end = after_open
+                .find("}}")
+                .ok_or_else(|| WorkflowError::TemplateError {
+                    message: format!("Unclosed template expression in: {}", template),
+                })?;
             let expr = after_open[..end].trim();
-            let parts: Vec<&str> = expr.splitn(3, '.').collect();
-            if parts.len() >= 2 {
-                refs.push((parts[0].to_string(), parts[1].to_string()));
-            }
-            rest = &after_open[end + 2..];
+            pos = start + 2 + end + 2;
+            found.push((start..pos, expr));
+        } else if let Some(after_open) = rest.strip_prefix("${")
+            && let Some(end) = after_open.find('}')
+            && is_reference(after_open[..end].trim())
+        {
+            pos = start + 2 + end + 1;
+            found.push((start..pos, after_open[..end].trim()));
         } else {
-            break;
+            pos = start + 1;
         }
     }
+    Ok(found)
+}
+
+fn is_reference(expr: &str) -> bool {
+    expr.starts_with("inputs.") || expr.starts_with("steps.")
+}
+
+/// The expression a string consists of entirely, if it is a single
+/// template expression with nothing around it.
+pub fn whole_expression(template: &str) -> Option<String> {
+    let trimmed = template.trim();
+    match scan(trimmed).ok()?.as_slice() {
+        [(range, expr)] if *range == (0..trimmed.len()) => Some(expr.to_string()),
+        _ => None,
+    }
+}
 
-    refs
+/// Extract the full dotted expressions referenced in a string, such as
+/// `inputs.path` or `steps.build.outputs.artifact_path`.
+pub fn extract_expressions(template: &str) -> Vec<String> {
+    scan(template)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(_, expr)| expr.to_string())
+        .collect()
+}
+
+/// Extract all template variable references from a string.
+/// Returns pairs like `("inputs", "path")` or `("steps", "fetch_pr")`.
+pub fn extract_references(template: &str) -> Vec<(String, String)> {
+    extract_expressions(template)
+        .iter()
+        .filter_map(|expr| {
+            let parts: Vec<&str> = expr.splitn(3, '.').collect();
+            (parts.len() >= 2).then(|| (parts[0].to_string(), parts[1].to_string()))
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -249,4 +361,53 @@ mod tests {
         let result2 = evaluate_condition("{{ steps.check.output }} != 'pass'", &ctx2).unwrap();
         assert!(!result2);
     }
+
+    #[test]
+    fn test_render_dollar_brace_syntax() {
+        let ctx = make_ctx(vec![("path", "src/main.rs")], vec![]);
+        let result = render_string("cat ${inputs.path} > $HOME/${OUT}", &ctx).unwrap();
+        assert_eq!(result, "cat src/main.rs > $HOME/${OUT}");
+    }
+
+    #[test]
+    fn test_render_named_outputs_keep_type() {
+        let mut build = HashMap::new();
+        build.insert("count".to_string(), serde_json::json!(3));
+        build.insert(
+            "report".to_string(),
+            serde_json::json!({"handle": "att-0123456789ab", "path": "/tmp/report.pdf"}),
+        );
+        build.insert("meta".to_string(), serde_json::json!({"files": ["a.rs"]}));
+        let mut named = HashMap::new();
+        named.insert("build".to_string(), build);
+        let ctx = make_ctx(vec![], vec![]).with_named_outputs(named);
+
+        let params = serde_json::json!({
+            "count": "${steps.build.outputs.count}",
+            "attachment": "{{ steps.build.outputs.report }}",
+            "path": "{{ steps.build.outputs.report.path }}",
+            "first": "${steps.build.outputs.meta.files.0}",
+            "text": "Built ${steps.build.outputs.count} files into ${steps.build.outputs.report}",
+        });
+        let rendered = render_value(&params, &ctx).unwrap();
+        assert_eq!(rendered["count"], serde_json::json!(3));
+        assert_eq!(rendered["attachment"], "att-0123456789ab");
+        assert_eq!(rendered["path"], "/tmp/report.pdf");
+        assert_eq!(rendered["first"], "a.rs");
+        assert_eq!(rendered["text"], "Built 3 files into att-0123456789ab");
+
+        let err = render_string("${steps.build.outputs.missing}", &ctx).unwrap_err();
+        assert!(err.to_string().contains("Output 'missing' of step 'build'"));
+    }
+
+    #[test]
+    fn test_extract_expressions() {
+        let exprs = extract_expressions("{{ inputs.a }} ${steps.b.outputs.c.d} ${PATH}");
+        assert_eq!(exprs, vec!["inputs.a", "steps.b.outputs.c.d"]);
+        assert_eq!(
+            whole_expression(" ${steps.b.outputs.c} ").as_deref(),
+            Some("steps.b.outputs.c")
+        );
+        assert!(whole_expression("x {{ inputs.a }}").is_none());
+    }
 }
@@ -3,8 +3,10 @@
 //! Defines the core data structures: workflow definitions, steps, gates,
 //! state tracking, and related enums.
 
+use crate::workflow::templates::extract_expressions;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt;
 use uuid::Uuid;
@@ -47,6 +49,13 @@ fn default_input_type() -> String {
     "string".to_string()
 }
 
+impl WorkflowInput {
+    /// The declared type, or `None` if it is not a known type name.
+    pub fn value_type(&self) -> Option<ValueType> {
+        ValueType::parse(&self.input_type)
+    }
+}
+
 /// A single step in a workflow execution plan.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowStep {
@@ -68,6 +77,62 @@ pub struct WorkflowStep {
     pub gate_preview: Option<String>,
     #[serde(default)]
     pub timeout_secs: Option<u64>,
+    /// Typed values this step publishes to later steps.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<StepOutput>,
+}
+
+impl WorkflowStep {
+    /// Template expressions this step reads, in params, its condition and
+    /// gate texts, such as `inputs.path` or `steps.build.outputs.report`.
+    pub fn references(&self) -> Vec<String> {
+        fn collect(value: &Value, refs: &mut Vec<String>) {
+            match value {
+                Value::String(s) => refs.extend(extract_expressions(s)),
+                Value::Array(items) => items.iter().for_each(|v| collect(v, refs)),
+                Value::Object(map) => map.values().for_each(|v| collect(v, refs)),
+                _ => {}
+            }
+        }
+        let mut refs = Vec::new();
+        let mut keys: Vec<&String> = self.params.keys().collect();
+        keys.sort();
+        for key in keys {
+            collect(&self.params[key], &mut refs);
+        }
+        for text in [&self.condition, &self.gate_message, &self.gate_preview]
+            .into_iter()
+            .flatten()
+        {
+            refs.extend(extract_expressions(text));
+        }
+        let mut seen = std::collections::HashSet::new();
+        refs.retain(|r| seen.insert(r.clone()));
+        refs
+    }
+}
+
+/// A typed value a step publishes, referenced by later steps as
+/// `${steps.<id>.outputs.<name>}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepOutput {
+    pub name: String,
+    #[serde(rename = "type", default = "default_input_type")]
+    pub output_type: String,
+    #[serde(default)]
+    pub description: String,
+    /// Dotted path to the value in the tool result. Defaults to the result
+    /// field named like the output, or the whole result when the step
+    /// declares a single output.
+    #[serde(default)]
+    pub from: Option<String>,
+}
+
+impl StepOutput {
+    /// The declared type, or `None` if it is not a known type name.
+    pub fn value_type(&self) -> Option<ValueType> {
+        ValueType::parse(&self.output_type)
+    }
 }
 
 /// An output declaration for a workflow.
@@ -75,6 +140,168 @@ pub struct WorkflowStep {
 pub struct WorkflowOutput {
     pub name: String,
     pub value: String,
+    /// Expected type of the value, checked when the workflow is validated.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub output_type: Option<String>,
+}
+
+/// Type of a workflow input or step output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueType {
+    String,
+    Number,
+    Boolean,
+    /// A single-line file system path.
+    Path,
+    /// Any JSON value; fields can be referenced with further dotted keys.
+    Json,
+    /// A file kept in the attachment store and passed on by handle.
+    Artifact,
+    /// A list, written `<type>[]`.
+    List(Box<ValueType>),
+}
+
+impl ValueType {
+    /// Parse a type name such as `string`, `path` or `string[]`.
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if let Some(inner) = name.strip_suffix("[]") {
+            return Self::parse(inner).map(|t| ValueType::List(Box::new(t)));
+        }
+        Some(match name.to_ascii_lowercase().as_str() {
+            "string" => ValueType::String,
+            "number" => ValueType::Number,
+            "boolean" | "bool" => ValueType::Boolean,
+            "path" => ValueType::Path,
+            "json" => ValueType::Json,
+            "artifact" => ValueType::Artifact,
+            _ => return None,
+        })
+    }
+
+    /// Whether a value of type `actual` can be used where `self` is expected.
+    pub fn accepts(&self, actual: &ValueType) -> bool {
+        match (self, actual) {
+            (ValueType::Json, _) => true,
+            (ValueType::List(expected), ValueType::List(actual)) => expected.accepts(actual),
+            (ValueType::String, actual) => !matches!(actual, ValueType::Json | ValueType::List(_)),
+            (expected, actual) => expected == actual,
+        }
+    }
+
+    /// Check `value` against this type, converting strings where the
+    /// conversion is unambiguous: `"3"` to a number, `"true"` to a boolean,
+    /// a JSON document to JSON, and `a,b` to a list.
+    ///
+    /// An `artifact` accepts a file path or an attachment handle; storing the
+    /// file is left to the caller.
+    pub fn coerce(&self, value: Value) -> Result<Value, String> {
+        let mismatch = |value: &Value| format!("expected {}, got {}", self, describe(value));
+        match (self, value) {
+            (ValueType::Json, Value::String(s)) => {
+                Ok(serde_json::from_str(&s).unwrap_or(Value::String(s)))
+            }
+            (ValueType::Json, value) => Ok(value),
+            (ValueType::String, value @ Value::String(_)) => Ok(value),
+            (ValueType::String, value @ (Value::Number(_) | Value::Bool(_))) => {
+                Ok(Value::String(value.to_string()))
+            }
+            (ValueType::Number, value @ Value::Number(_)) => Ok(value),
+            (ValueType::Number, Value::String(s)) => {
+                let trimmed = s.trim();
+                let number = trimmed.parse::<i64>().map(Value::from).ok().or_else(|| {
+                    trimmed
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(serde_json::Number::from_f64)
+                        .map(Value::Number)
+                });
+                number.ok_or_else(|| mismatch(&Value::String(s)))
+            }
+            (ValueType::Boolean, value @ Value::Bool(_)) => Ok(value),
+            (ValueType::Boolean, Value::String(s)) => {
+                let lower = s.trim().to_ascii_lowercase();
+                match lower.as_str() {
+                    "true" | "yes" => Ok(Value::Bool(true)),
+                    "false" | "no" => Ok(Value::Bool(false)),
+                    _ => Err(mismatch(&Value::String(s))),
+                }
+            }
+            (ValueType::Path, Value::String(s)) if is_path_like(&s) => {
+                Ok(Value::String(s.trim().to_string()))
+            }
+            (ValueType::Artifact, Value::String(s)) if is_path_like(&s) => {
+                Ok(Value::String(s.trim().to_string()))
+            }
+            (ValueType::Artifact, Value::Object(map))
+                if map.get("path").is_some_and(Value::is_string) =>
+            {
+                Ok(Value::Object(map))
+            }
+            (ValueType::List(inner), Value::Array(items)) => items
+                .into_iter()
+                .map(|item| inner.coerce(item))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+            (ValueType::List(inner), Value::String(s)) => {
+                if s.trim_start().starts_with('[')
+                    && let Ok(items @ Value::Array(_)) = serde_json::from_str::<Value>(&s)
+                {
+                    return self.coerce(items);
+                }
+                s.split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| inner.coerce(Value::String(item.to_string())))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Value::Array)
+            }
+            (_, value) => Err(mismatch(&value)),
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueType::String => write!(f, "string"),
+            ValueType::Number => write!(f, "number"),
+            ValueType::Boolean => write!(f, "boolean"),
+            ValueType::Path => write!(f, "path"),
+            ValueType::Json => write!(f, "json"),
+            ValueType::Artifact => write!(f, "artifact"),
+            ValueType::List(inner) => write!(f, "{}[]", inner),
+        }
+    }
+}
+
+/// A path is a single non-empty line; anything else is prose.
+fn is_path_like(s: &str) -> bool {
+    let s = s.trim();
+    !s.is_empty() && !s.contains('\n') && s.len() <= 4096
+}
+
+/// Short description of a value for type errors.
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(s) if s.lines().count() > 1 => {
+            format!("{} lines of text", s.lines().count())
+        }
+        Value::String(s) if s.trim().is_empty() => "an empty string".to_string(),
+        Value::String(s) => {
+            let short: String = s.chars().take(40).collect();
+            if short.len() < s.len() {
+                format!("'{}…'", short)
+            } else {
+                format!("'{}'", s)
+            }
+        }
+        Value::Null => "nothing".to_string(),
+        Value::Bool(b) => format!("boolean {}", b),
+        Value::Number(n) => format!("number {}", n),
+        Value::Array(_) => "a list".to_string(),
+        Value::Object(_) => "a JSON object".to_string(),
+    }
 }
 
 /// Type of approval gate on a workflow step.
@@ -159,6 +386,9 @@ pub struct WorkflowState {
     pub status: WorkflowStatus,
     pub current_step_index: usize,
     pub step_outputs: HashMap<String, serde_json::Value>,
+    /// Declared outputs of each completed step, by step ID and output name.
+    #[serde(default)]
+    pub named_outputs: HashMap<String, HashMap<String, serde_json::Value>>,
     pub inputs: HashMap<String, serde_json::Value>,
     pub started_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -175,6 +405,7 @@ impl WorkflowState {
             status: WorkflowStatus::Pending,
             current_step_index: 0,
             step_outputs: HashMap::new(),
+            named_outputs: HashMap::new(),
             inputs,
             started_at: now,
             updated_at: now,
@@ -270,10 +501,12 @@ mod tests {
                 gate_message: None,
                 gate_preview: None,
                 timeout_secs: None,
+                outputs: Vec::new(),
             }],
             outputs: vec![WorkflowOutput {
                 name: "result".to_string(),
                 value: "{{ steps.step1.output }}".to_string(),
+                output_type: None,
             }],
         };
 
@@ -354,6 +587,7 @@ mod tests {
             gate_message: None,
             gate_preview: None,
             timeout_secs: Some(60),
+            outputs: Vec::new(),
         };
 
         let json = serde_json::to_string(&step).unwrap();
@@ -364,4 +598,77 @@ mod tests {
         );
         assert_eq!(deserialized.timeout_secs, Some(60));
     }
+
+    #[test]
+    fn test_value_type_parse_and_display() {
+        assert_eq!(ValueType::parse("path"), Some(ValueType::Path));
+        assert_eq!(
+            ValueType::parse("string[]"),
+            Some(ValueType::List(Box::new(ValueType::String)))
+        );
+        assert_eq!(ValueType::parse("blob"), None);
+        assert_eq!(
+            ValueType::parse("number[]").unwrap().to_string(),
+            "number[]"
+        );
+    }
+
+    #[test]
+    fn test_value_type_coerce() {
+        use serde_json::json;
+        assert_eq!(ValueType::Number.coerce(json!("42")).unwrap(), json!(42));
+        assert_eq!(ValueType::Number.coerce(json!("2.5")).unwrap(), json!(2.5));
+        let err = ValueType::Number.coerce(json!("lots")).unwrap_err();
+        assert_eq!(err, "expected number, got 'lots'");
+        assert_eq!(
+            ValueType::Boolean.coerce(json!("yes")).unwrap(),
+            json!(true)
+        );
+        assert_eq!(
+            ValueType::Json.coerce(json!("{\"a\": 1}")).unwrap(),
+            json!({"a": 1})
+        );
+        assert_eq!(
+            ValueType::Path.coerce(json!(" out/report.pdf ")).unwrap(),
+            json!("out/report.pdf")
+        );
+        let err = ValueType::Path
+            .coerce(json!("The report is ready.\nIt lives in out/."))
+            .unwrap_err();
+        assert!(err.contains("expected path, got 2 lines of text"));
+        let list = ValueType::parse("number[]").unwrap();
+        assert_eq!(list.coerce(json!("1, 2,3")).unwrap(), json!([1, 2, 3]));
+        assert_eq!(list.coerce(json!("[4, 5]")).unwrap(), json!([4, 5]));
+        assert!(list.coerce(json!("1,x")).is_err());
+    }
+
+    #[test]
+    fn test_value_type_accepts() {
+        assert!(ValueType::String.accepts(&ValueType::Path));
+        assert!(ValueType::Json.accepts(&ValueType::Artifact));
+        assert!(!ValueType::Path.accepts(&ValueType::String));
+        assert!(!ValueType::Number.accepts(&ValueType::Json));
+        assert!(!ValueType::String.accepts(&ValueType::Json));
+    }
+
+    #[test]
+    fn test_step_outputs_parse_from_yaml() {
+        let step: WorkflowStep = serde_yaml::from_str(
+            r#"
+id: build
+tool: pdf_generate
+outputs:
+  - name: artifact_path
+    type: artifact
+    from: path
+  - name: pages
+    type: number
+"#,
+        )
+        .unwrap();
+        assert_eq!(step.outputs.len(), 2);
+        assert_eq!(step.outputs[0].value_type(), Some(ValueType::Artifact));
+        assert_eq!(step.outputs[0].from.as_deref(), Some("path"));
+        assert_eq!(step.outputs[1].value_type(), Some(ValueType::Number));
+    }
 }
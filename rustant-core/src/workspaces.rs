@@ -0,0 +1,237 @@
+//! Named workspaces — short names for project directories.
+//!
+//! `rustant workspace add ~/code/acme --name acme` records the directory
+//! under a name so `--workspace-name acme` and the REPL's `/workspace acme`
+//! can switch to it. Everything an agent keeps per workspace (facts,
+//! checkpoints, sessions, scheduler state) stays under the workspace's own
+//! `.rustant/` directory; the registry only maps names to paths.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const WORKSPACES_FILE: &str = "workspaces.json";
+
+/// Default location of the named-workspace registry.
+pub fn default_workspaces_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("dev", "rustant", "rustant")
+        .map(|d| d.data_dir().join(WORKSPACES_FILE))
+}
+
+/// Errors managing named workspaces.
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceError {
+    #[error("Failed to access workspace registry {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Corrupt workspace registry {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+    #[error(
+        "Invalid workspace name '{name}': use letters, digits, '-', '_' or '.', not starting with '.'"
+    )]
+    InvalidName { name: String },
+    #[error("Workspace '{name}' already exists ({path})")]
+    AlreadyExists { name: String, path: PathBuf },
+    #[error("Workspace directory {path} does not exist")]
+    MissingDirectory { path: PathBuf },
+    #[error("Unknown workspace '{name}'. Add it with `rustant workspace add <path> --name {name}`")]
+    NotFound { name: String },
+}
+
+/// A project directory registered under a short name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedWorkspace {
+    pub name: String,
+    /// Canonical path of the workspace directory.
+    pub path: PathBuf,
+    pub added_at: DateTime<Utc>,
+}
+
+/// The set of named workspaces, persisted as JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceRegistry {
+    #[serde(default)]
+    workspaces: Vec<NamedWorkspace>,
+}
+
+impl WorkspaceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the registry, or an empty one if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self, WorkspaceError> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(source) => {
+                return Err(WorkspaceError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                });
+            }
+        };
+        serde_json::from_str(&content).map_err(|e| WorkspaceError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Write the registry atomically (temp file + rename).
+    pub fn save(&self, path: &Path) -> Result<(), WorkspaceError> {
+        let io_err = |source| WorkspaceError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_err)?;
+        }
+        let json = serde_json::to_string_pretty(self).expect("workspace registry serializes");
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(io_err)?;
+        std::fs::rename(&tmp, path).map_err(io_err)
+    }
+
+    /// Register `path` under `name`. The directory must exist; it is stored
+    /// canonicalized so the same project is recognised however it is spelled.
+    pub fn add(&mut self, name: &str, path: &Path) -> Result<&NamedWorkspace, WorkspaceError> {
+        if !is_valid_name(name) {
+            return Err(WorkspaceError::InvalidName {
+                name: name.to_string(),
+            });
+        }
+        if let Some(existing) = self.get(name) {
+            return Err(WorkspaceError::AlreadyExists {
+                name: name.to_string(),
+                path: existing.path.clone(),
+            });
+        }
+        let path = path
+            .canonicalize()
+            .ok()
+            .filter(|p| p.is_dir())
+            .ok_or_else(|| WorkspaceError::MissingDirectory {
+                path: path.to_path_buf(),
+            })?;
+        self.workspaces.push(NamedWorkspace {
+            name: name.to_string(),
+            path,
+            added_at: Utc::now(),
+        });
+        self.workspaces.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(self.get(name).expect("workspace just added"))
+    }
+
+    /// Unregister a workspace. Its directory and data are left untouched.
+    pub fn remove(&mut self, name: &str) -> Result<NamedWorkspace, WorkspaceError> {
+        let index = self
+            .workspaces
+            .iter()
+            .position(|w| w.name == name)
+            .ok_or_else(|| WorkspaceError::NotFound {
+                name: name.to_string(),
+            })?;
+        Ok(self.workspaces.remove(index))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&NamedWorkspace> {
+        self.workspaces.iter().find(|w| w.name == name)
+    }
+
+    /// Directory of the workspace called `name`, which must still exist.
+    pub fn resolve(&self, name: &str) -> Result<PathBuf, WorkspaceError> {
+        let workspace = self.get(name).ok_or_else(|| WorkspaceError::NotFound {
+            name: name.to_string(),
+        })?;
+        if !workspace.path.is_dir() {
+            return Err(WorkspaceError::MissingDirectory {
+                path: workspace.path.clone(),
+            });
+        }
+        Ok(workspace.path.clone())
+    }
+
+    /// Name registered for the directory `path`, if any.
+    pub fn name_for(&self, path: &Path) -> Option<&str> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.workspaces
+            .iter()
+            .find(|w| w.path == path)
+            .map(|w| w.name.as_str())
+    }
+
+    /// All named workspaces, sorted by name.
+    pub fn list(&self) -> &[NamedWorkspace] {
+        &self.workspaces
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.workspaces.is_empty()
+    }
+}
+
+/// Display label for `path`: its registered name, else the path itself.
+pub fn workspace_label(registry: &WorkspaceRegistry, path: &Path) -> String {
+    registry
+        .name_for(path)
+        .map(str::to_string)
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_resolve_and_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let acme = dir.path().join("acme");
+        std::fs::create_dir(&acme).unwrap();
+        let store = dir.path().join("workspaces.json");
+
+        let mut registry = WorkspaceRegistry::new();
+        registry.add("acme", &acme).unwrap();
+        registry.save(&store).unwrap();
+
+        let loaded = WorkspaceRegistry::load(&store).unwrap();
+        let path = loaded.resolve("acme").unwrap();
+        assert_eq!(path, acme.canonicalize().unwrap());
+        assert_eq!(loaded.name_for(&acme), Some("acme"));
+        assert_eq!(workspace_label(&loaded, &acme), "acme");
+    }
+
+    #[test]
+    fn test_add_rejects_bad_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = WorkspaceRegistry::new();
+        assert!(matches!(
+            registry.add("../etc", dir.path()),
+            Err(WorkspaceError::InvalidName { .. })
+        ));
+        assert!(matches!(
+            registry.add("gone", &dir.path().join("missing")),
+            Err(WorkspaceError::MissingDirectory { .. })
+        ));
+        registry.add("home", dir.path()).unwrap();
+        assert!(matches!(
+            registry.add("home", dir.path()),
+            Err(WorkspaceError::AlreadyExists { .. })
+        ));
+        assert!(matches!(
+            registry.resolve("other"),
+            Err(WorkspaceError::NotFound { .. })
+        ));
+        registry.remove("home").unwrap();
+        assert!(registry.is_empty());
+    }
+}
@@ -0,0 +1,63 @@
+//! Compatibility check of the gateway protocol against the committed schema
+//! snapshot.
+//!
+//! Adding optional fields, messages or enum values passes. Removing,
+//! renaming or retyping anything fails until `PROTOCOL_VERSION`'s major
+//! version is bumped and the snapshot is refreshed with
+//! `RUSTANT_UPDATE_PROTOCOL_SNAPSHOT=1 cargo test -p rustant-core --test gateway_protocol_schema`.
+
+use rustant_core::gateway::{PROTOCOL_VERSION, breaking_changes, protocol_major, protocol_schema};
+use std::path::Path;
+
+const SNAPSHOT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/snapshots/gateway_protocol.json"
+);
+
+fn write_snapshot(schema: &serde_json::Value) {
+    let path = Path::new(SNAPSHOT);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let json = serde_json::to_string_pretty(schema).unwrap();
+    std::fs::write(path, json + "\n").unwrap();
+}
+
+#[test]
+fn test_protocol_schema_is_backward_compatible() {
+    let current = protocol_schema();
+    if std::env::var_os("RUSTANT_UPDATE_PROTOCOL_SNAPSHOT").is_some() {
+        write_snapshot(&current);
+        return;
+    }
+    assert!(
+        Path::new(SNAPSHOT).exists(),
+        "{} is missing; generate it with RUSTANT_UPDATE_PROTOCOL_SNAPSHOT=1 \
+         cargo test -p rustant-core --test gateway_protocol_schema and commit it",
+        SNAPSHOT
+    );
+
+    let snapshot: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(SNAPSHOT).unwrap()).unwrap();
+    let snapshot_version = snapshot["protocol_version"].as_str().unwrap_or_default();
+    let breaks = breaking_changes(&snapshot, &current);
+    if protocol_major(snapshot_version) == protocol_major(PROTOCOL_VERSION) {
+        assert!(
+            breaks.is_empty(),
+            "Breaking gateway protocol changes against {} without a major version bump:\n  {}",
+            snapshot_version,
+            breaks.join("\n  ")
+        );
+    } else {
+        panic!(
+            "PROTOCOL_VERSION is {} but the snapshot is {}; refresh the snapshot with \
+             RUSTANT_UPDATE_PROTOCOL_SNAPSHOT=1",
+            PROTOCOL_VERSION, snapshot_version
+        );
+    }
+}
+
+#[test]
+fn test_protocol_version_is_major_minor() {
+    let parts: Vec<&str> = PROTOCOL_VERSION.split('.').collect();
+    assert_eq!(parts.len(), 2);
+    assert!(parts.iter().all(|p| p.parse::<u32>().is_ok()));
+}
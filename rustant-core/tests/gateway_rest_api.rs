@@ -4,7 +4,8 @@
 
 use axum::body::Body;
 use rustant_core::gateway::{
-    GatewayConfig, GatewayServer, PendingApproval, SharedGateway, gateway_router,
+    GatewayConfig, GatewayServer, PROTOCOL_HEADER, PROTOCOL_VERSION, PendingApproval,
+    SharedGateway, gateway_router,
 };
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -59,6 +60,27 @@ async fn test_api_status_returns_json() {
     assert_eq!(json["total_llm_requests"], 0);
 }
 
+#[tokio::test]
+async fn test_rest_responses_carry_protocol_version() {
+    let (_, json) = get_json(make_gateway(), "/api/status").await;
+    assert_eq!(json["protocol_version"], PROTOCOL_VERSION);
+
+    for uri in ["/api/status", "/health", "/api/sessions"] {
+        let app = gateway_router(make_gateway());
+        let resp = ServiceExt::<axum::http::Request<Body>>::oneshot(app, make_request(uri))
+            .await
+            .unwrap();
+        assert_eq!(
+            resp.headers()
+                .get(PROTOCOL_HEADER)
+                .and_then(|v| v.to_str().ok()),
+            Some(PROTOCOL_VERSION),
+            "{}",
+            uri
+        );
+    }
+}
+
 #[tokio::test]
 async fn test_api_status_reflects_metrics() {
     let gw = make_gateway();
@@ -117,6 +139,28 @@ async fn test_api_sessions_with_active() {
     assert!(sessions[0].get("created_at").is_some());
 }
 
+#[tokio::test]
+async fn test_api_sessions_show_workspace() {
+    let gw = make_gateway();
+    {
+        let mut g = gw.lock().await;
+        g.set_default_workspace("home");
+        g.sessions_mut()
+            .create_session_in(Uuid::new_v4(), Some("acme".to_string()));
+        g.sessions_mut()
+            .create_session_in(Uuid::new_v4(), Some("acme".to_string()));
+    }
+    let (_, json) = get_json(gw.clone(), "/api/sessions").await;
+    assert_eq!(json["sessions"][0]["workspace"], "acme");
+
+    let (_, json) = get_json(gw, "/api/status").await;
+    assert_eq!(json["default_workspace"], "home");
+    let workspaces = json["workspaces"].as_array().unwrap();
+    assert_eq!(workspaces.len(), 1);
+    assert_eq!(workspaces[0]["name"], "acme");
+    assert_eq!(workspaces[0]["sessions"], 2);
+}
+
 // --- /api/config ---
 
 #[tokio::test]
@@ -162,6 +206,7 @@ async fn test_api_metrics() {
     assert_eq!(json["total_tool_calls"], 5);
     assert_eq!(json["total_llm_requests"], 3);
     assert!(json["uptime_secs"].as_u64().is_some());
+    assert!(json["llm_providers"].is_array());
 }
 
 // --- /api/audit ---
@@ -175,6 +220,92 @@ async fn test_api_audit_returns_empty() {
     assert!(json["entries"].as_array().unwrap().is_empty());
 }
 
+// --- /api/cron/{name}/history ---
+
+#[tokio::test]
+async fn test_api_cron_history() {
+    let gw = make_gateway();
+    let (status, _) = get_json(gw.clone(), "/api/cron/email/history").await;
+    assert_eq!(status, 404);
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut history = rustant_core::JobHistory::new(10);
+    history.record(
+        rustant_core::JobRun::finished(
+            "email",
+            chrono::Utc::now(),
+            rustant_core::RunOutcome::Failed,
+        )
+        .with_error("provider timeout"),
+    );
+    history.save(dir.path()).unwrap();
+    gw.lock().await.set_cron_state_dir(dir.path());
+
+    let (status, json) = get_json(gw, "/api/cron/email/history").await;
+    assert_eq!(status, 200);
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["runs"][0]["outcome"], "failed");
+    assert_eq!(json["runs"][0]["error"], "provider timeout");
+}
+
+// --- /api/stats/cost ---
+
+#[tokio::test]
+async fn test_api_cost_report_and_cap_status() {
+    use rustant_core::event_store::{CostCap, Event, EventRecord, EventStore};
+
+    let gw = make_gateway();
+    let (status, _) = get_json(gw.clone(), "/api/stats/cost").await;
+    assert_eq!(status, 404);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("events.db");
+    let llm = |workspace: &str, model: &str, cost: Option<f64>| EventRecord {
+        at: chrono::Utc::now(),
+        workspace: workspace.into(),
+        event: Event::LlmRequest {
+            model: model.into(),
+            success: true,
+            latency_ms: 300,
+            input_tokens: 1000,
+            output_tokens: 100,
+            cost_usd: cost,
+        },
+    };
+    EventStore::open(&path)
+        .unwrap()
+        .write(&[
+            llm("/work", "gpt-4o", Some(3.0)),
+            llm("/work", "local", None),
+            llm("/home", "gpt-4o", Some(0.5)),
+        ])
+        .unwrap();
+    let mut config = rustant_core::AgentConfig::default();
+    config.event_store.enabled = true;
+    config.event_store.path = Some(path.clone());
+    config.budget = Some(rustant_core::config::BudgetConfig {
+        monthly_soft_cap_usd: 2.0,
+        ..Default::default()
+    });
+    let cap = CostCap::from_config(&config, std::path::Path::new("/work"));
+    gw.lock().await.set_cost_tracking(&path, cap);
+
+    let (status, json) = get_json(gw.clone(), "/api/stats/cost?workspace=/work").await;
+    assert_eq!(status, 200);
+    assert_eq!(json["total_usd"], 3.5);
+    assert_eq!(json["unpriced_requests"], 1);
+    assert_eq!(json["rows"][0]["workspace"], "/work");
+    assert_eq!(json["chart"]["datasets"][0]["data"][0], 3.0);
+    assert_eq!(json["chart"]["annotations"][0]["value"], 2.0);
+
+    let (status, _) = get_json(gw.clone(), "/api/stats/cost?month=2025-13").await;
+    assert_eq!(status, 400);
+
+    let (_, json) = get_json(gw, "/api/status").await;
+    assert_eq!(json["cost_cap"]["exceeded"], true);
+    assert_eq!(json["cost_cap"]["spent_usd"], 3.0);
+}
+
 // --- /api/approvals ---
 
 #[tokio::test]
@@ -196,6 +327,10 @@ async fn test_api_approvals_with_pending() {
             tool_name: "shell_exec".into(),
             description: "rm -rf /tmp/test".into(),
             risk_level: "high".into(),
+            created_at: chrono::Utc::now(),
+            expires_at: None,
+            escalated: false,
+            policy: None,
         });
     }
     let (_, json) = get_json(gw, "/api/approvals").await;
@@ -218,6 +353,10 @@ async fn test_api_approval_decision_approve() {
             tool_name: "file_write".into(),
             description: "Write to config".into(),
             risk_level: "medium".into(),
+            created_at: chrono::Utc::now(),
+            expires_at: None,
+            escalated: false,
+            policy: None,
         });
     }
 
@@ -288,3 +427,156 @@ impl rustant_core::gateway::StatusProvider for TestStatusProvider {
         vec![("macbook".into(), "healthy".into())]
     }
 }
+
+// --- /api/artifacts ---
+
+fn file_artifact(path: &std::path::Path) -> rustant_core::ArtifactRecord {
+    rustant_core::ArtifactRecord::from_artifact(
+        "pdf_generate",
+        &rustant_core::Artifact::FileCreated {
+            path: path.to_path_buf(),
+        },
+        None,
+        None,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_api_artifacts_marks_deleted_files_stale() {
+    let dir = tempfile::tempdir().unwrap();
+    let kept = dir.path().join("report.pdf");
+    let removed = dir.path().join("old.pdf");
+    std::fs::write(&kept, "pdf").unwrap();
+    let gw = make_gateway();
+    {
+        let mut g = gw.lock().await;
+        g.record_artifact(file_artifact(&kept));
+        g.record_artifact(file_artifact(&removed));
+    }
+    let (status, json) = get_json(gw, "/api/artifacts").await;
+    assert_eq!(status, 200);
+    assert_eq!(json["total"], 2);
+    let artifacts = json["artifacts"].as_array().unwrap();
+    assert_eq!(artifacts[0]["kind"], "report");
+    assert_eq!(artifacts[0]["source_tool"], "pdf_generate");
+    assert_eq!(artifacts[0]["stale"], false);
+    assert_eq!(artifacts[1]["stale"], true);
+}
+
+#[tokio::test]
+async fn test_api_artifact_open_stale_and_canvas() {
+    let gw = make_gateway();
+    let stale = file_artifact(std::path::Path::new("/nonexistent/rustant/old.pdf"));
+    let canvas = rustant_core::ArtifactRecord::from_artifact(
+        "canvas_push",
+        &rustant_core::Artifact::CanvasItem {
+            id: "item-1".into(),
+            target: String::new(),
+            content_type: "markdown".into(),
+            content: "# Hi".into(),
+        },
+        None,
+        None,
+    )
+    .unwrap();
+    let (stale_id, canvas_id) = (stale.id, canvas.id);
+    let mut events = {
+        let mut g = gw.lock().await;
+        g.record_artifact(stale);
+        g.record_artifact(canvas);
+        g.subscribe()
+    };
+
+    let post = |uri: String| {
+        let app = gateway_router(gw.clone());
+        ServiceExt::<axum::http::Request<Body>>::oneshot(
+            app,
+            make_post_request(&uri, serde_json::json!({})),
+        )
+    };
+    let resp = post(format!("/api/artifacts/{}/open", stale_id))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 410);
+
+    let resp = post(format!("/api/artifacts/{}/open", canvas_id))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    match events.try_recv().unwrap() {
+        rustant_core::gateway::GatewayEvent::CanvasRepush { artifact_id, .. } => {
+            assert_eq!(artifact_id, canvas_id)
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    let resp = post(format!("/api/artifacts/{}/open", Uuid::new_v4()))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+// --- /api/forms ---
+
+#[tokio::test]
+async fn test_api_form_submit_validates_and_first_wins() {
+    let gw = make_gateway();
+    let spec = serde_json::from_value(serde_json::json!({
+        "title": "Login",
+        "fields": [
+            {"name": "user", "label": "User", "field_type": "email", "required": true},
+            {"name": "password", "label": "Password", "field_type": "text",
+             "required": true, "sensitive": true, "default_value": "hunter2"}
+        ]
+    }))
+    .unwrap();
+    let form = rustant_core::gateway::PendingForm::new(spec, None);
+    let form_id = form.id;
+    gw.lock().await.add_form(form);
+
+    let (status, json) = get_json(gw.clone(), "/api/forms").await;
+    assert_eq!(status, 200);
+    assert_eq!(json["forms"][0]["id"], form_id.to_string());
+    assert!(json["forms"][0]["spec"]["fields"][1]["default_value"].is_null());
+
+    let submit = |values: serde_json::Value| {
+        let app = gateway_router(gw.clone());
+        async move {
+            let resp = ServiceExt::<axum::http::Request<Body>>::oneshot(
+                app,
+                make_post_request(
+                    &format!("/api/forms/{}/submit", form_id),
+                    serde_json::json!({ "values": values }),
+                ),
+            )
+            .await
+            .unwrap();
+            let status = resp.status();
+            let body = axum::body::to_bytes(resp.into_body(), 100_000)
+                .await
+                .unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            (status, json)
+        }
+    };
+
+    let (status, json) = submit(serde_json::json!({"user": "ada"})).await;
+    assert_eq!(status, 422);
+    assert_eq!(json["errors"][0]["field"], "user");
+    assert_eq!(json["errors"][1]["field"], "password");
+
+    let (status, json) =
+        submit(serde_json::json!({"user": "ada@example.com", "password": "s3cret"})).await;
+    assert_eq!(status, 200);
+    assert_eq!(json["values"]["password"], "[redacted]");
+
+    let (status, _) =
+        submit(serde_json::json!({"user": "bob@example.com", "password": "other"})).await;
+    assert_eq!(status, 409);
+
+    let (_, json) = get_json(gw, "/api/audit").await;
+    let details = json["entries"][0]["details"].as_str().unwrap();
+    assert!(details.contains("ada@example.com"));
+    assert!(!details.contains("s3cret"));
+}
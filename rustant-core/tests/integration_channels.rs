@@ -35,7 +35,7 @@ async fn test_telegram_real_auth() {
     let token =
         std::env::var("RUSTANT_TEST_TELEGRAM_TOKEN").expect("RUSTANT_TEST_TELEGRAM_TOKEN not set");
     let config = rustant_core::channels::telegram::TelegramConfig {
-        bot_token: token,
+        bot_token: token.into(),
         ..Default::default()
     };
     let mut ch = rustant_core::channels::telegram::create_telegram_channel(config);
@@ -78,7 +78,7 @@ async fn test_matrix_real_auth() {
         std::env::var("RUSTANT_TEST_MATRIX_TOKEN").expect("RUSTANT_TEST_MATRIX_TOKEN not set");
     let config = rustant_core::channels::matrix::MatrixConfig {
         homeserver_url: homeserver,
-        access_token: token,
+        access_token: token.into(),
         ..Default::default()
     };
     let mut ch = rustant_core::channels::matrix::create_matrix_channel(config);
@@ -456,11 +456,12 @@ async fn test_gmail_imap_xoauth2_real_connection() {
         smtp_host: "smtp.gmail.com".into(),
         smtp_port: 587,
         username: email.clone(),
-        password: token,
+        password: token.into(),
         password_env: None,
         from_address: email,
         allowed_senders: vec![],
         auth_method: EmailAuthMethod::XOAuth2,
+        ..Default::default()
     };
     let mut ch = create_email_channel(config);
     ch.connect()
@@ -479,7 +480,7 @@ fn test_email_auth_method_xoauth2_config() {
         smtp_host: "smtp.gmail.com".into(),
         smtp_port: 587,
         username: "user@gmail.com".into(),
-        password: String::new(),
+        password: Default::default(),
         auth_method: EmailAuthMethod::XOAuth2,
         ..Default::default()
     };
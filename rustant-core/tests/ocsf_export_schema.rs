@@ -0,0 +1,90 @@
+//! Validates OCSF audit export against the OCSF 1.1.0 API Activity JSON
+//! schema in `tests/schemas/`, so SIEM-facing field mapping can't regress
+//! silently.
+
+use rustant_core::audit::{
+    AuditExporter, AuditQuery, AuditStore, ExecutionTrace, OCSF_VERSION, TraceEventKind,
+};
+use rustant_core::types::RiskLevel;
+use uuid::Uuid;
+
+const SCHEMA: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/schemas/ocsf-1.1.0-api_activity.json"
+);
+
+fn schema() -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(SCHEMA).unwrap()).unwrap()
+}
+
+/// Export every tool request and execution in a store covering each risk
+/// level and both outcomes.
+fn api_activity_events() -> Vec<serde_json::Value> {
+    let mut store = AuditStore::with_merkle_chain();
+    let mut trace = ExecutionTrace::new(Uuid::new_v4(), Uuid::new_v4(), "schema check");
+    let risks = [
+        RiskLevel::ReadOnly,
+        RiskLevel::Write,
+        RiskLevel::Execute,
+        RiskLevel::Network,
+        RiskLevel::Destructive,
+    ];
+    for (i, risk) in risks.into_iter().enumerate() {
+        let tool = format!("tool_{}", i);
+        trace.push_event(TraceEventKind::ToolRequested {
+            tool: tool.clone(),
+            risk_level: risk,
+            args_summary: "path=\"src/main.rs\"".into(),
+        });
+        trace.push_event(TraceEventKind::ToolExecuted {
+            tool,
+            success: i % 2 == 0,
+            duration_ms: 10 * i as u64,
+            output_preview: "ok".into(),
+        });
+    }
+    trace.complete(true);
+    store.add_trace(trace);
+
+    let query = AuditQuery::new()
+        .for_event_type("tool_requested")
+        .for_event_type("tool_executed");
+    let mut buf = Vec::new();
+    let meta = AuditExporter::write_ocsf(&store, &query, &mut buf).unwrap();
+    assert_eq!(meta.events, 10);
+    assert_eq!(meta.ocsf_version.as_deref(), Some(OCSF_VERSION));
+
+    String::from_utf8(buf)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_api_activity_events_match_ocsf_schema() {
+    let schema = schema();
+    for event in api_activity_events() {
+        assert_eq!(event["class_uid"], 6003);
+        let errors: Vec<String> = jsonschema::validator_for(&schema)
+            .unwrap()
+            .iter_errors(&event)
+            .map(|e| format!("{} at {}", e, e.instance_path))
+            .collect();
+        assert!(
+            errors.is_empty(),
+            "OCSF API Activity schema violations:\n  {}\nevent: {}",
+            errors.join("\n  "),
+            event
+        );
+    }
+}
+
+#[test]
+fn test_schema_rejects_missing_required_attribute() {
+    let schema = schema();
+    let mut event = api_activity_events().remove(0);
+    assert!(jsonschema::is_valid(&schema, &event));
+    event.as_object_mut().unwrap().remove("api");
+    assert!(!jsonschema::is_valid(&schema, &event));
+}
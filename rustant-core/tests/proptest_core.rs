@@ -9,8 +9,10 @@ use rustant_core::injection::InjectionDetector;
 use rustant_core::memory::{Fact, LongTermMemory, ShortTermMemory};
 use rustant_core::merkle::MerkleChain;
 use rustant_core::scheduler::{CronJob, CronJobConfig, CronScheduler};
+use rustant_core::similarity::{Signature, SimilarityConfig, SimilarityService};
 use rustant_core::skills::parse_skill_md;
 use rustant_core::updater::is_newer_version;
+use unicode_normalization::UnicodeNormalization;
 
 // --- Version comparison properties ---
 
@@ -341,3 +343,68 @@ proptest! {
         prop_assert!(!store.has_key(&provider));
     }
 }
+
+// --- Similarity properties ---
+
+fn inbox_similarity() -> SimilarityService {
+    SimilarityService::new(SimilarityConfig::inbox())
+}
+
+proptest! {
+    #[test]
+    fn similarity_ignores_unicode_composition(text in "\\PC{0,40}") {
+        let composed: String = text.nfc().collect();
+        let decomposed: String = text.nfd().collect();
+        prop_assert!(inbox_similarity().compare(&composed, &decomposed).is_some());
+    }
+
+    #[test]
+    fn similarity_ignores_whitespace_and_punctuation(
+        words in prop::collection::vec("[a-z0-9]{1,8}", 1..12),
+        separators in prop::collection::vec(prop::sample::select(vec![" ", "  ", "\t", "\n", " - ", ", ", "; "]), 12),
+    ) {
+        let plain = words.join(" ");
+        let mut spaced = String::from("  ");
+        for (word, sep) in words.iter().zip(&separators) {
+            spaced.push_str(word);
+            spaced.push_str(sep);
+        }
+        let score = inbox_similarity().compare(&plain, &spaced);
+        prop_assert!(score.is_some_and(|s| s.score >= 1.0), "{:?}", score);
+    }
+
+    #[test]
+    fn similarity_ignores_case_and_accents(words in prop::collection::vec("[a-z]{1,8}", 1..12)) {
+        let plain = words.join(" ");
+        let shouted = plain.to_uppercase().replace('E', "\u{c9}");
+        prop_assert!(inbox_similarity().compare(&plain, &shouted).is_some_and(|s| s.score >= 1.0));
+    }
+
+    #[test]
+    fn similarity_ignores_word_order(
+        shuffled in prop::collection::vec("[a-z]{1,8}", 1..12).prop_flat_map(|words| {
+            (Just(words.clone()), Just(words).prop_shuffle())
+        }),
+    ) {
+        let (words, reordered) = shuffled;
+        prop_assert!(inbox_similarity().compare(&words.join(" "), &reordered.join(" ")).is_some());
+    }
+
+    #[test]
+    fn similarity_keeps_different_numbers_apart(
+        words in prop::collection::vec("[a-z]{1,8}", 1..12),
+        n in 0u32..10_000,
+    ) {
+        let a = format!("{} {}", words.join(" "), n);
+        let b = format!("{} {}", words.join(" "), n + 1);
+        prop_assert!(inbox_similarity().compare(&a, &b).is_none());
+    }
+
+    #[test]
+    fn similarity_signature_roundtrips(text in "\\PC{0,60}") {
+        let signature = Signature::of(&text);
+        let json = serde_json::to_string(&signature).unwrap();
+        let back: Signature = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(back, signature);
+    }
+}
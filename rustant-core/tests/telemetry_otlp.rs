@@ -0,0 +1,192 @@
+//! Smoke tests for OpenTelemetry export against an in-process OTLP/HTTP mock.
+//!
+//! Run with `cargo test -p rustant-core --features otel --test telemetry_otlp`.
+
+#![cfg(feature = "otel")]
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::routing::post;
+use rustant_core::Agent;
+use rustant_core::agent::{RecordingCallback, RegisteredTool};
+use rustant_core::brain::MockLlmProvider;
+use rustant_core::config::AgentConfig;
+use rustant_core::credentials::{CredentialStore, InMemoryCredentialStore};
+use rustant_core::secret_ref::SecretRef;
+use rustant_core::telemetry::{OtlpProtocol, Telemetry, TelemetryConfig};
+use rustant_core::types::{RiskLevel, ToolDefinition, ToolOutput};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Requests received by the mock collector: (path, authorization header, body).
+type Received = Arc<Mutex<Vec<(String, Option<String>, Value)>>>;
+
+async fn start_collector() -> (String, Received) {
+    let received: Received = Arc::default();
+    let record = |path: &'static str| {
+        move |State(received): State<Received>, headers: HeaderMap, body: Bytes| async move {
+            let auth = headers
+                .get("authorization")
+                .and_then(|v| v.to_str().ok())
+                .map(String::from);
+            let json = serde_json::from_slice(&body).unwrap_or(Value::Null);
+            received
+                .lock()
+                .unwrap()
+                .push((path.to_string(), auth, json));
+            "{}"
+        }
+    };
+    let app = Router::new()
+        .route("/v1/traces", post(record("/v1/traces")))
+        .route("/v1/metrics", post(record("/v1/metrics")))
+        .with_state(received.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{addr}"), received)
+}
+
+fn agent_with_echo_tool() -> Agent {
+    let provider = Arc::new(MockLlmProvider::new());
+    provider.queue_response(MockLlmProvider::tool_call_response(
+        "echo",
+        serde_json::json!({"text": "hi"}),
+    ));
+    provider.queue_response(MockLlmProvider::text_response("done"));
+
+    let mut config = AgentConfig::default();
+    config.llm.use_streaming = false;
+    let mut agent = Agent::new(provider, config, Arc::new(RecordingCallback::new()));
+    agent.register_tool(RegisteredTool {
+        definition: ToolDefinition {
+            name: "echo".to_string(),
+            description: "Echo input text back".to_string(),
+            parameters: serde_json::json!({"type": "object"}),
+        },
+        risk_level: RiskLevel::ReadOnly,
+        executor: Box::new(|args: serde_json::Value| {
+            Box::pin(async move { Ok(ToolOutput::text(args["text"].to_string())) })
+        }),
+    });
+    agent
+}
+
+/// Flatten OTLP JSON attributes into `key -> string value`.
+fn attributes(span: &Value) -> std::collections::HashMap<String, String> {
+    span["attributes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|attr| {
+            let key = attr["key"].as_str()?.to_string();
+            let value = &attr["value"];
+            let value = value["stringValue"]
+                .as_str()
+                .map(String::from)
+                .or_else(|| value["intValue"].as_str().map(String::from))
+                .or_else(|| value["intValue"].as_i64().map(|v| v.to_string()))?;
+            Some((key, value))
+        })
+        .collect()
+}
+
+fn spans(received: &Received) -> Vec<Value> {
+    received
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(path, _, _)| path == "/v1/traces")
+        .flat_map(|(_, _, body)| {
+            body["resourceSpans"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+        })
+        .flat_map(|rs| rs["scopeSpans"].as_array().cloned().unwrap_or_default())
+        .flat_map(|ss| ss["spans"].as_array().cloned().unwrap_or_default())
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_tool_call_exports_span_attributes_and_metrics() {
+    let (endpoint, received) = start_collector().await;
+    let store = InMemoryCredentialStore::new();
+    store.store_key("otel-token", "Bearer test-token").unwrap();
+    let mut config = TelemetryConfig {
+        enabled: true,
+        endpoint,
+        protocol: OtlpProtocol::HttpJson,
+        service_name: "rustant-test".into(),
+        ..Default::default()
+    };
+    config
+        .headers
+        .insert("authorization".into(), SecretRef::keychain("otel-token"));
+    let telemetry = Telemetry::init(&config, &store).unwrap().unwrap();
+
+    let subscriber = tracing_subscriber::registry().with(telemetry.tracing_layer());
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let mut agent = agent_with_echo_tool();
+    let result = agent.process_task("echo hi").await.unwrap();
+    assert!(result.success);
+
+    let flush = tokio::task::spawn_blocking(move || {
+        telemetry.force_flush();
+        telemetry
+    });
+    let _telemetry = flush.await.unwrap();
+
+    let spans = spans(&received);
+    let tool_span = spans
+        .iter()
+        .find(|s| s["name"] == "tool_call")
+        .expect("tool_call span exported");
+    let attrs = attributes(tool_span);
+    assert_eq!(attrs["tool_name"], "echo");
+    assert_eq!(attrs["task_id"], result.task_id.to_string());
+    assert_eq!(attrs["session_id"], agent.session_id().to_string());
+    assert_eq!(attrs["outcome"], "success");
+
+    let llm_span = spans
+        .iter()
+        .find(|s| s["name"] == "llm_request")
+        .expect("llm_request span exported");
+    assert_eq!(attributes(llm_span)["model"], "mock-model");
+
+    let task_span = spans
+        .iter()
+        .find(|s| s["name"] == "task")
+        .expect("task span exported");
+    assert_eq!(attributes(task_span)["task_id"], result.task_id.to_string());
+    assert_eq!(tool_span["traceId"], task_span["traceId"]);
+
+    let received = received.lock().unwrap();
+    assert!(
+        received
+            .iter()
+            .all(|(_, auth, _)| auth.as_deref() == Some("Bearer test-token"))
+    );
+    let traces_body = received
+        .iter()
+        .find(|(path, _, _)| path == "/v1/traces")
+        .map(|(_, _, body)| body.to_string())
+        .unwrap();
+    assert!(traces_body.contains("rustant-test"));
+    let metrics_body = received
+        .iter()
+        .filter(|(path, _, _)| path == "/v1/metrics")
+        .map(|(_, _, body)| body.to_string())
+        .collect::<String>();
+    for name in [
+        "rustant.tool.calls",
+        "rustant.llm.requests",
+        "rustant.llm.tokens",
+    ] {
+        assert!(metrics_body.contains(name), "missing metric {name}");
+    }
+}
@@ -0,0 +1,70 @@
+//! OpenTelemetry export must never stall the agent when the collector is down.
+//!
+//! Kept in its own test binary: the exported instruments are process-wide.
+//! Run with `cargo test -p rustant-core --features otel --test telemetry_otlp_unreachable`.
+
+#![cfg(feature = "otel")]
+
+use rustant_core::Agent;
+use rustant_core::agent::{RecordingCallback, RegisteredTool};
+use rustant_core::brain::MockLlmProvider;
+use rustant_core::config::AgentConfig;
+use rustant_core::credentials::InMemoryCredentialStore;
+use rustant_core::telemetry::{Telemetry, TelemetryConfig};
+use rustant_core::types::{RiskLevel, ToolDefinition, ToolOutput};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing_subscriber::layer::SubscriberExt;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_unreachable_collector_does_not_stall_agent() {
+    // Bind then drop a listener so the port refuses connections.
+    let port = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let config = TelemetryConfig {
+        enabled: true,
+        endpoint: format!("http://127.0.0.1:{port}"),
+        max_queue_size: 4,
+        export_timeout_secs: 1,
+        ..Default::default()
+    };
+    let telemetry = Telemetry::init(&config, &InMemoryCredentialStore::new())
+        .unwrap()
+        .unwrap();
+    let subscriber = tracing_subscriber::registry().with(telemetry.tracing_layer());
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let provider = Arc::new(MockLlmProvider::new());
+    for _ in 0..20 {
+        provider.queue_response(MockLlmProvider::tool_call_response(
+            "echo",
+            serde_json::json!({"text": "hi"}),
+        ));
+    }
+    provider.queue_response(MockLlmProvider::text_response("done"));
+    let mut config = AgentConfig::default();
+    config.llm.use_streaming = false;
+    let mut agent = Agent::new(provider, config, Arc::new(RecordingCallback::new()));
+    agent.register_tool(RegisteredTool {
+        definition: ToolDefinition {
+            name: "echo".to_string(),
+            description: "Echo input text back".to_string(),
+            parameters: serde_json::json!({"type": "object"}),
+        },
+        risk_level: RiskLevel::ReadOnly,
+        executor: Box::new(|args: serde_json::Value| {
+            Box::pin(async move { Ok(ToolOutput::text(args["text"].to_string())) })
+        }),
+    });
+
+    // Far more spans than the 4-span queue holds; the excess is dropped.
+    let started = Instant::now();
+    assert!(agent.process_task("echo hi").await.unwrap().success);
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    tokio::task::spawn_blocking(move || drop(telemetry))
+        .await
+        .unwrap();
+}
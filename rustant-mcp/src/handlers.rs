@@ -3,8 +3,9 @@
 use crate::error::McpError;
 use crate::protocol::{
     CallToolParams, CallToolResult, InitializeParams, InitializeResult, ListResourcesResult,
-    ListToolsResult, MCP_PROTOCOL_VERSION, McpTool, ReadResourceParams, ReadResourceResult,
-    ResourcesCapability, ServerCapabilities, ServerInfo, ToolContent, ToolsCapability,
+    ListToolsResult, MCP_PROTOCOL_VERSION, McpTool, McpToolAnnotations, ReadResourceParams,
+    ReadResourceResult, ResourcesCapability, ServerCapabilities, ServerInfo, ToolContent,
+    ToolsCapability,
 };
 use crate::resources::ResourceManager;
 use rustant_core::config::McpSafetyConfig;
@@ -172,9 +173,15 @@ impl RequestHandler {
         }
 
         let definitions = self.tool_registry.list_definitions();
+        let aliases = self.tool_registry.aliases();
         let tools: Vec<McpTool> = definitions
             .into_iter()
             .map(|def| McpTool {
+                annotations: aliases.get(&def.name).map(|alias| McpToolAnnotations {
+                    deprecated: Some(true),
+                    replaced_by: Some(alias.canonical.clone()),
+                    removed_in: alias.removed_in.clone(),
+                }),
                 name: def.name,
                 description: Some(def.description),
                 input_schema: def.parameters,
@@ -551,11 +558,11 @@ mod tests {
 
         let result = handler.handle_tools_list().unwrap();
         let tools = result["tools"].as_array().unwrap();
-        // 40 base + 3 iMessage + 24 macOS native = 67 on macOS
+        // 45 base + 3 iMessage + 24 macOS native = 72 on macOS
         #[cfg(target_os = "macos")]
-        assert_eq!(tools.len(), 67);
+        assert_eq!(tools.len(), 72);
         #[cfg(not(target_os = "macos"))]
-        assert_eq!(tools.len(), 40);
+        assert_eq!(tools.len(), 45);
 
         // Check that each tool has required fields
         for tool in tools {
@@ -564,6 +571,63 @@ mod tests {
         }
     }
 
+    /// Tool renamed from "old_ping", for alias listing.
+    struct PingTool;
+
+    #[async_trait::async_trait]
+    impl rustant_tools::registry::Tool for PingTool {
+        fn name(&self) -> &str {
+            "ping"
+        }
+
+        fn description(&self) -> &str {
+            "Reply with pong"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(
+            &self,
+            _args: Value,
+        ) -> Result<rustant_core::types::ToolOutput, rustant_core::error::ToolError> {
+            Ok(rustant_core::types::ToolOutput::text("pong"))
+        }
+
+        fn risk_level(&self) -> rustant_core::types::RiskLevel {
+            rustant_core::types::RiskLevel::ReadOnly
+        }
+
+        fn aliases(&self) -> Vec<rustant_core::tool_aliases::ToolAlias> {
+            vec![
+                rustant_core::tool_aliases::ToolAlias::new("old_ping", "ping", "1.0.0")
+                    .removed_in("99.0.0"),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_tools_list_annotates_deprecated_aliases() {
+        let dir = TempDir::new().unwrap();
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(PingTool)).unwrap();
+        registry.set_list_aliases(true);
+        let resource_manager = ResourceManager::new(dir.path().to_path_buf());
+        let mut handler = RequestHandler::new(Arc::new(registry), resource_manager);
+        handler.handle_initialize(init_params()).unwrap();
+
+        let result = handler.handle_tools_list().unwrap();
+        let tools = result["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 2);
+        let alias = tools.iter().find(|t| t["name"] == "old_ping").unwrap();
+        assert_eq!(alias["annotations"]["deprecated"], true);
+        assert_eq!(alias["annotations"]["replacedBy"], "ping");
+        assert_eq!(alias["annotations"]["removedIn"], "99.0.0");
+        let tool = tools.iter().find(|t| t["name"] == "ping").unwrap();
+        assert!(tool.get("annotations").is_none());
+    }
+
     #[tokio::test]
     async fn test_tools_call_not_initialized() {
         let (mut handler, _dir) = create_test_handler();
@@ -237,11 +237,11 @@ mod tests {
         .to_string();
         let resp = server.process_message(&list_req).await.unwrap().unwrap();
         let tools = resp.result.unwrap()["tools"].as_array().unwrap().clone();
-        // 40 base + 3 iMessage + 24 macOS native = 67 on macOS
+        // 45 base + 3 iMessage + 24 macOS native = 72 on macOS
         #[cfg(target_os = "macos")]
-        assert_eq!(tools.len(), 67);
+        assert_eq!(tools.len(), 72);
         #[cfg(not(target_os = "macos"))]
-        assert_eq!(tools.len(), 40);
+        assert_eq!(tools.len(), 45);
     }
 
     #[tokio::test]
@@ -329,11 +329,11 @@ mod tests {
         let resp_str = client.read_message().await.unwrap().unwrap();
         let resp: JsonRpcResponse = serde_json::from_str(&resp_str).unwrap();
         let tools = resp.result.unwrap()["tools"].as_array().unwrap().clone();
-        // 40 base + 3 iMessage + 24 macOS native = 67 on macOS
+        // 45 base + 3 iMessage + 24 macOS native = 72 on macOS
         #[cfg(target_os = "macos")]
-        assert_eq!(tools.len(), 67);
+        assert_eq!(tools.len(), 72);
         #[cfg(not(target_os = "macos"))]
-        assert_eq!(tools.len(), 40);
+        assert_eq!(tools.len(), 45);
 
         // 4. Call echo tool
         let call_req = json!({
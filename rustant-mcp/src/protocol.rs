@@ -282,6 +282,24 @@ pub struct McpTool {
     pub description: Option<String>,
     /// JSON Schema describing the expected input.
     pub input_schema: Value,
+    /// Extra information about the tool, such as a deprecation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<McpToolAnnotations>,
+}
+
+/// Annotations on a listed tool.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolAnnotations {
+    /// The tool is a deprecated alias and may be removed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<bool>,
+    /// Name of the tool to call instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
+    /// Version in which the tool stops working.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub removed_in: Option<String>,
 }
 
 /// Result for `tools/list`.
@@ -671,6 +689,7 @@ mod tests {
                 },
                 "required": ["path"]
             }),
+            annotations: None,
         };
         let serialized = serde_json::to_value(&tool).unwrap();
         assert_eq!(serialized["name"], "read_file");
@@ -692,9 +711,11 @@ mod tests {
             name: "ping".into(),
             description: None,
             input_schema: json!({"type": "object"}),
+            annotations: None,
         };
         let s = serde_json::to_value(&tool_no_desc).unwrap();
         assert!(s.get("description").is_none());
+        assert!(s.get("annotations").is_none());
         let d: McpTool = serde_json::from_value(s).unwrap();
         assert!(d.description.is_none());
     }
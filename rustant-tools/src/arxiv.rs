@@ -3,7 +3,7 @@
 use async_trait::async_trait;
 use chrono::Utc;
 use rustant_core::error::ToolError;
-use rustant_core::types::{RiskLevel, ToolOutput};
+use rustant_core::types::{Artifact, RiskLevel, ToolOutput};
 use serde_json::{Value, json};
 use std::path::PathBuf;
 use std::time::Duration;
@@ -13,8 +13,14 @@ use crate::arxiv_api::{
     ImplementationMode, ImplementationRecord, ImplementationStatus, LibraryEntry, ProjectScaffold,
     ScaffoldFile, generate_bibtex, language_config,
 };
+use crate::paper_sources::{
+    PaperFilters, PaperRanking, PaperRecord, PaperSource, PaperSourcesClient, rank,
+};
 use crate::registry::Tool;
 
+/// Maximum number of `paper_search` results kept for follow-up actions.
+const SEARCH_CACHE_LIMIT: usize = 200;
+
 pub struct ArxivResearchTool {
     workspace: PathBuf,
 }
@@ -67,6 +73,90 @@ impl ArxivResearchTool {
         Ok(())
     }
 
+    fn search_cache_path(&self) -> PathBuf {
+        self.workspace
+            .join(".rustant")
+            .join("arxiv")
+            .join("search_results.json")
+    }
+
+    fn load_search_cache(&self) -> Vec<PaperRecord> {
+        std::fs::read_to_string(self.search_cache_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Remember `records` so later actions can reference them by `paper_id`.
+    /// Newer results replace older copies of the same paper.
+    fn save_search_cache(&self, records: &[PaperRecord]) -> Result<(), ToolError> {
+        let mut cache = records.to_vec();
+        cache.extend(
+            self.load_search_cache()
+                .into_iter()
+                .filter(|old| !records.iter().any(|r| r.id == old.id)),
+        );
+        cache.truncate(SEARCH_CACHE_LIMIT);
+
+        let path = self.search_cache_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| ToolError::ExecutionFailed {
+                name: "arxiv_research".to_string(),
+                message: format!("Create dir failed: {}", e),
+            })?;
+        }
+        let json =
+            serde_json::to_string_pretty(&cache).map_err(|e| ToolError::ExecutionFailed {
+                name: "arxiv_research".to_string(),
+                message: format!("Serialize failed: {}", e),
+            })?;
+        std::fs::write(&path, json).map_err(|e| ToolError::ExecutionFailed {
+            name: "arxiv_research".to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Look up a `paper_search` result by its id, DOI, or arXiv id.
+    fn find_cached_paper(&self, reference: &str) -> Result<PaperRecord, ToolError> {
+        self.load_search_cache()
+            .into_iter()
+            .find(|r| r.matches_reference(reference))
+            .ok_or_else(|| ToolError::InvalidArguments {
+                name: "arxiv_research".to_string(),
+                reason: format!(
+                    "Unknown paper_id '{}'. Use an id from a previous paper_search result.",
+                    reference
+                ),
+            })
+    }
+
+    /// The arXiv id given directly, or the one behind a `paper_id`.
+    /// Returns `Ok(Err(record))` when the referenced paper has no arXiv version.
+    fn resolve_arxiv_id(
+        &self,
+        args: &Value,
+        action: &str,
+    ) -> Result<Result<String, PaperRecord>, ToolError> {
+        if let Some(id) = args.get("arxiv_id").and_then(|v| v.as_str()) {
+            return Ok(Ok(id.to_string()));
+        }
+        let paper_id = args
+            .get("paper_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidArguments {
+                name: "arxiv_research".to_string(),
+                reason: format!(
+                    "Missing required parameter 'arxiv_id' (or 'paper_id') for {} action",
+                    action
+                ),
+            })?;
+        let record = self.find_cached_paper(paper_id)?;
+        Ok(match record.arxiv_id.clone() {
+            Some(id) => Ok(id),
+            None => Err(record),
+        })
+    }
+
     fn make_client(&self) -> Result<ArxivClient, ToolError> {
         ArxivClient::new().map_err(|e| ToolError::ExecutionFailed {
             name: "arxiv_research".to_string(),
@@ -148,23 +238,138 @@ impl ArxivResearchTool {
         Ok(ToolOutput::text(output))
     }
 
-    async fn handle_fetch(&self, args: &Value) -> Result<ToolOutput, ToolError> {
-        let arxiv_id = args
-            .get("arxiv_id")
+    async fn handle_paper_search(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let query = args.get("query").and_then(|v| v.as_str()).ok_or_else(|| {
+            ToolError::InvalidArguments {
+                name: "arxiv_research".to_string(),
+                reason: "Missing required parameter 'query' for paper_search action".to_string(),
+            }
+        })?;
+
+        let max_results = args
+            .get("max_results")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(10)
+            .min(50) as usize;
+
+        let sources: Vec<PaperSource> = match args.get("sources").and_then(|v| v.as_array()) {
+            Some(arr) => arr
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| {
+                    PaperSource::from_str_loose(s).ok_or_else(|| ToolError::InvalidArguments {
+                        name: "arxiv_research".to_string(),
+                        reason: format!(
+                            "Unknown source '{}'. Valid sources: arxiv, semantic_scholar, openreview",
+                            s
+                        ),
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+            None => PaperSource::ALL.to_vec(),
+        };
+
+        let year = |key: &str| args.get(key).and_then(|v| v.as_i64()).map(|y| y as i32);
+        let filters = PaperFilters {
+            year_from: year("year_from"),
+            year_to: year("year_to"),
+            venues: args
+                .get("venues")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            min_citations: args.get("min_citations").and_then(|v| v.as_u64()),
+        };
+
+        let ranking = args
+            .get("rank_by")
             .and_then(|v| v.as_str())
-            .ok_or_else(|| ToolError::InvalidArguments {
+            .map(PaperRanking::from_str_loose)
+            .unwrap_or(PaperRanking::Relevance);
+
+        let client = PaperSourcesClient::new().map_err(|e| ToolError::ExecutionFailed {
+            name: "arxiv_research".to_string(),
+            message: e,
+        })?;
+        // Over-fetch so filtering still leaves enough results to show.
+        let outcome = client.search(query, &sources, max_results * 2).await;
+
+        if outcome.skipped.len() == sources.len() {
+            let reasons: Vec<String> = outcome
+                .skipped
+                .iter()
+                .map(|s| format!("{}: {}", s.source.as_str(), s.reason))
+                .collect();
+            return Err(ToolError::ExecutionFailed {
                 name: "arxiv_research".to_string(),
-                reason: "Missing required parameter 'arxiv_id' for fetch action".to_string(),
-            })?;
+                message: format!("All paper sources failed ({})", reasons.join("; ")),
+            });
+        }
+
+        let mut records: Vec<PaperRecord> = outcome
+            .records
+            .into_iter()
+            .filter(|r| filters.matches(r))
+            .collect();
+        rank(&mut records, ranking);
+        records.truncate(max_results);
+        self.save_search_cache(&records)?;
+
+        let mut output = if records.is_empty() {
+            format!("No papers found for query: \"{}\"\n", query)
+        } else {
+            format!("Found {} papers:\n\n", records.len())
+        };
+        for (i, record) in records.iter().enumerate() {
+            output.push_str(&format!(
+                "{}. **{}** [{}]\n   Authors: {}\n   {}\n   Abstract: {}\n{}\n",
+                i + 1,
+                record.title,
+                record.id,
+                record.authors.join(", "),
+                record_facts(record),
+                truncate_text(&record.abstract_text, 200),
+                record
+                    .pdf_url
+                    .as_ref()
+                    .map(|u| format!("   PDF: {}\n", u))
+                    .unwrap_or_default(),
+            ));
+        }
+        for skipped in &outcome.skipped {
+            output.push_str(&format!(
+                "Note: {} was skipped ({}); results may be incomplete.\n",
+                skipped.source.as_str(),
+                skipped.reason
+            ));
+        }
+        if !records.is_empty() {
+            output.push_str("---\nReference a result by the id in brackets via 'paper_id' (fetch, save) or 'paper_ids' (export_bibtex) without searching again.");
+        }
+
+        Ok(ToolOutput::text(output.trim_end().to_string()))
+    }
+
+    async fn handle_fetch(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let arxiv_id = match self.resolve_arxiv_id(args, "fetch")? {
+            Ok(id) => id,
+            // Not on arXiv: the search record is all there is to show.
+            Err(record) => return Ok(ToolOutput::text(format_paper_record(&record))),
+        };
 
         let client = self.make_client()?;
-        let paper = client
-            .fetch_paper(arxiv_id)
-            .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "arxiv_research".to_string(),
-                message: e,
-            })?;
+        let paper =
+            client
+                .fetch_paper(&arxiv_id)
+                .await
+                .map_err(|e| ToolError::ExecutionFailed {
+                    name: "arxiv_research".to_string(),
+                    message: e,
+                })?;
 
         let output = format!(
             "**{}**\n\nAuthors: {}\nArXiv ID: {}\nCategories: {}\nPrimary Category: {}\nPublished: {}\nUpdated: {}\nPDF: {}\nAbstract URL: {}{}{}{}\n\n**Abstract:**\n{}",
@@ -398,13 +603,16 @@ impl ArxivResearchTool {
     }
 
     async fn handle_save(&self, args: &Value) -> Result<ToolOutput, ToolError> {
-        let arxiv_id = args
-            .get("arxiv_id")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| ToolError::InvalidArguments {
-                name: "arxiv_research".to_string(),
-                reason: "Missing required parameter 'arxiv_id' for save action".to_string(),
-            })?;
+        let arxiv_id =
+            self.resolve_arxiv_id(args, "save")?
+                .map_err(|record| ToolError::InvalidArguments {
+                    name: "arxiv_research".to_string(),
+                    reason: format!(
+                        "'{}' has no arXiv version; the library only holds arXiv papers. \
+                     Use export_bibtex with paper_ids to cite it instead.",
+                        record.id
+                    ),
+                })?;
 
         let tags: Vec<String> = args
             .get("tags")
@@ -433,13 +641,14 @@ impl ArxivResearchTool {
 
         // Fetch the paper
         let client = self.make_client()?;
-        let paper = client
-            .fetch_paper(arxiv_id)
-            .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "arxiv_research".to_string(),
-                message: e,
-            })?;
+        let paper =
+            client
+                .fetch_paper(&arxiv_id)
+                .await
+                .map_err(|e| ToolError::ExecutionFailed {
+                    name: "arxiv_research".to_string(),
+                    message: e,
+                })?;
 
         let mut state = self.load_state();
 
@@ -587,6 +796,36 @@ impl ArxivResearchTool {
                     .collect()
             });
 
+        let paper_ids: Vec<String> = args
+            .get("paper_ids")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !paper_ids.is_empty() {
+            let records = paper_ids
+                .iter()
+                .map(|id| self.find_cached_paper(id))
+                .collect::<Result<Vec<_>, _>>()?;
+            let bibtex = records
+                .iter()
+                .map(PaperRecord::to_bibtex)
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            return Ok(ToolOutput::text(format!(
+                "BibTeX export ({} entries):\n\n{}",
+                records.len(),
+                bibtex
+            ))
+            .with_artifact(Artifact::Data {
+                mime_type: "application/x-bibtex".into(),
+                data: bibtex,
+            }));
+        }
+
         let state = self.load_state();
 
         let papers_to_export: Vec<_> = if let Some(ids) = &specific_ids {
@@ -615,7 +854,11 @@ impl ArxivResearchTool {
             "BibTeX export ({} entries):\n\n{}",
             papers_to_export.len(),
             bibtex.trim_end()
-        )))
+        ))
+        .with_artifact(Artifact::Data {
+            mime_type: "application/x-bibtex".into(),
+            data: bibtex.trim_end().to_string(),
+        }))
     }
 
     fn handle_collections(&self, args: &Value) -> Result<ToolOutput, ToolError> {
@@ -1321,8 +1564,56 @@ fn truncate_text(text: &str, max_len: usize) -> String {
     if text.len() <= max_len {
         text.to_string()
     } else {
-        format!("{}...", &text[..max_len])
+        let mut end = max_len;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}...", &text[..end])
+    }
+}
+
+/// One line of year, venue, identifiers, citations, and sources for a search record.
+fn record_facts(record: &PaperRecord) -> String {
+    let mut facts = Vec::new();
+    if let Some(year) = record.year {
+        facts.push(format!("Year: {}", year));
     }
+    if let Some(venue) = &record.venue {
+        facts.push(format!("Venue: {}", venue));
+    }
+    if let Some(doi) = &record.doi {
+        facts.push(format!("DOI: {}", doi));
+    }
+    if let Some(arxiv_id) = &record.arxiv_id {
+        facts.push(format!("arXiv: {}", arxiv_id));
+    }
+    if let Some(count) = record.citation_count {
+        facts.push(format!("Citations: {}", count));
+    }
+    let sources: Vec<&str> = record.sources.iter().map(|s| s.as_str()).collect();
+    facts.push(format!("Sources: {}", sources.join(", ")));
+    facts.join(" | ")
+}
+
+/// Full details of a search record that has no arXiv version to fetch.
+fn format_paper_record(record: &PaperRecord) -> String {
+    format!(
+        "**{}**\n\nAuthors: {}\nID: {}\n{}{}\n\n**Abstract:**\n{}",
+        record.title,
+        record.authors.join(", "),
+        record.id,
+        record_facts(record),
+        record
+            .pdf_url
+            .as_ref()
+            .map(|u| format!("\nPDF: {}", u))
+            .unwrap_or_default(),
+        if record.abstract_text.is_empty() {
+            "(not available)"
+        } else {
+            &record.abstract_text
+        },
+    )
 }
 
 /// Generate a project scaffold for a paper implementation.
@@ -1573,9 +1864,10 @@ impl Tool for ArxivResearchTool {
     }
 
     fn description(&self) -> &str {
-        "Search, fetch, analyze, and implement academic papers from arXiv. Actions: search, fetch, \
-         analyze, compare, trending, save/library/remove, export_bibtex, collections, \
-         digest_config, paper_to_code, paper_to_notebook, implement (full TDD project scaffold), \
+        "Search, fetch, analyze, and implement academic papers from arXiv. Actions: search, \
+         paper_search (unified arXiv + Semantic Scholar + OpenReview search with dedup, filters, \
+         and stable paper ids), fetch, analyze, compare, trending, save/library/remove, \
+         export_bibtex, collections, digest_config, paper_to_code, paper_to_notebook, implement (full TDD project scaffold), \
          setup_env (environment setup), verify (lint/test/typecheck), implementation_status. \
          IMPORTANT workflow: after 'search', present numbered results with summaries and ask the \
          user to select a paper. After selection, for 'implement'/'paper_to_code'/'paper_to_notebook', \
@@ -1590,7 +1882,7 @@ impl Tool for ArxivResearchTool {
                 "action": {
                     "type": "string",
                     "enum": [
-                        "search", "fetch", "analyze", "compare", "trending",
+                        "search", "paper_search", "fetch", "analyze", "compare", "trending",
                         "save", "library", "remove", "export_bibtex",
                         "collections", "digest_config", "paper_to_code", "paper_to_notebook",
                         "implement", "setup_env", "verify", "implementation_status"
@@ -1599,7 +1891,46 @@ impl Tool for ArxivResearchTool {
                 },
                 "query": {
                     "type": "string",
-                    "description": "Search query (for search action)"
+                    "description": "Search query (for search/paper_search actions)"
+                },
+                "sources": {
+                    "type": "array",
+                    "items": {
+                        "type": "string",
+                        "enum": ["arxiv", "semantic_scholar", "openreview"]
+                    },
+                    "description": "Backends for paper_search (default: all)"
+                },
+                "year_from": {
+                    "type": "integer",
+                    "description": "Earliest publication year (for paper_search)"
+                },
+                "year_to": {
+                    "type": "integer",
+                    "description": "Latest publication year (for paper_search)"
+                },
+                "venues": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Venue names to keep, matched case-insensitively, e.g. ['NeurIPS', 'ICLR'] (for paper_search)"
+                },
+                "min_citations": {
+                    "type": "integer",
+                    "description": "Minimum citation count (for paper_search)"
+                },
+                "rank_by": {
+                    "type": "string",
+                    "enum": ["relevance", "citations", "recency"],
+                    "description": "Ranking for paper_search results (default: relevance)"
+                },
+                "paper_id": {
+                    "type": "string",
+                    "description": "Id of a paper_search result, e.g. 'doi:10.1000/xyz' or 'arxiv:1706.03762' (for fetch/save)"
+                },
+                "paper_ids": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Ids of paper_search results (for export_bibtex)"
                 },
                 "arxiv_id": {
                     "type": "string",
@@ -1716,6 +2047,7 @@ impl Tool for ArxivResearchTool {
 
         match action {
             "search" => self.handle_search(&args).await,
+            "paper_search" => self.handle_paper_search(&args).await,
             "fetch" => self.handle_fetch(&args).await,
             "analyze" => self.handle_analyze(&args).await,
             "compare" => self.handle_compare(&args).await,
@@ -1735,7 +2067,7 @@ impl Tool for ArxivResearchTool {
             _ => Err(ToolError::InvalidArguments {
                 name: "arxiv_research".to_string(),
                 reason: format!(
-                    "Unknown action '{}'. Valid actions: search, fetch, analyze, compare, trending, save, library, remove, export_bibtex, collections, digest_config, paper_to_code, paper_to_notebook, implement, setup_env, verify, implementation_status",
+                    "Unknown action '{}'. Valid actions: search, paper_search, fetch, analyze, compare, trending, save, library, remove, export_bibtex, collections, digest_config, paper_to_code, paper_to_notebook, implement, setup_env, verify, implementation_status",
                     action
                 ),
             }),
@@ -1785,8 +2117,9 @@ mod tests {
         let schema = tool.parameters_schema();
         let actions = schema["properties"]["action"]["enum"].as_array().unwrap();
         let action_strs: Vec<&str> = actions.iter().filter_map(|v| v.as_str()).collect();
-        assert_eq!(action_strs.len(), 17);
+        assert_eq!(action_strs.len(), 18);
         assert!(action_strs.contains(&"search"));
+        assert!(action_strs.contains(&"paper_search"));
         assert!(action_strs.contains(&"fetch"));
         assert!(action_strs.contains(&"analyze"));
         assert!(action_strs.contains(&"compare"));
@@ -1919,6 +2252,60 @@ mod tests {
         assert!(result.content.contains("No papers to export"));
     }
 
+    #[tokio::test]
+    async fn test_paper_search_rejects_unknown_source() {
+        let dir = TempDir::new().unwrap();
+        let tool = ArxivResearchTool::new(dir.path().to_path_buf());
+        let result = tool
+            .execute(json!({"action": "paper_search", "query": "x", "sources": ["pubmed"]}))
+            .await;
+        match result.unwrap_err() {
+            ToolError::InvalidArguments { reason, .. } => assert!(reason.contains("pubmed")),
+            other => panic!("Expected InvalidArguments, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paper_id_follow_ups_use_search_cache() {
+        let dir = TempDir::new().unwrap();
+        let tool = ArxivResearchTool::new(dir.path().to_path_buf());
+        let records: Vec<PaperRecord> = serde_json::from_value(json!([{
+            "id": "paper:0123456789abcdef",
+            "title": "Sparse Mixtures Revisited",
+            "authors": ["Jane Doe"],
+            "year": 2023,
+            "venue": "ICLR 2023",
+            "abstract": "We revisit sparse mixtures.",
+            "pdf_url": "https://openreview.net/pdf?id=abc123",
+            "sources": ["openreview"]
+        }]))
+        .unwrap();
+        tool.save_search_cache(&records).unwrap();
+
+        let fetched = tool
+            .execute(json!({"action": "fetch", "paper_id": "paper:0123456789abcdef"}))
+            .await
+            .unwrap();
+        assert!(fetched.content.contains("Sparse Mixtures Revisited"));
+        assert!(fetched.content.contains("Venue: ICLR 2023"));
+
+        let bib = tool
+            .execute(json!({"action": "export_bibtex", "paper_ids": ["paper:0123456789abcdef"]}))
+            .await
+            .unwrap();
+        assert!(bib.content.contains("@inproceedings{doe2023sparse,"));
+
+        let save = tool
+            .execute(json!({"action": "save", "paper_id": "paper:0123456789abcdef"}))
+            .await;
+        assert!(matches!(save, Err(ToolError::InvalidArguments { .. })));
+
+        let unknown = tool
+            .execute(json!({"action": "fetch", "paper_id": "doi:10.1/none"}))
+            .await;
+        assert!(matches!(unknown, Err(ToolError::InvalidArguments { .. })));
+    }
+
     #[tokio::test]
     async fn test_collections_empty() {
         let dir = TempDir::new().unwrap();
@@ -794,7 +794,7 @@ fn generate_cite_key(paper: &ArxivPaper) -> String {
 }
 
 /// Escape special LaTeX characters in BibTeX fields.
-fn escape_bibtex(s: &str) -> String {
+pub(crate) fn escape_bibtex(s: &str) -> String {
     s.replace('&', "\\&")
         .replace('%', "\\%")
         .replace('$', "\\$")
@@ -0,0 +1,300 @@
+//! Briefing sections backed by tool state, and the cross-platform `briefing` tool.
+//!
+//! [`briefing_engine`] builds a [`BriefingEngine`] with the core sections plus
+//! calendar (macOS Calendar.app), flashcards, deadlines, finance and job-search
+//! sections read from the workspace's tool data.
+
+use async_trait::async_trait;
+use rustant_core::BriefingDelivery;
+use rustant_core::briefing::{
+    ActivitySection, BriefingContext, BriefingEngine, BriefingPeriod, BriefingSection,
+    InboxSection, PeopleSection, SystemSection,
+};
+use rustant_core::config::AgentConfig;
+use rustant_core::error::ToolError;
+use rustant_core::types::{RiskLevel, ToolOutput};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::registry::Tool;
+
+/// Engine with the core sections and every tool-backed section registered,
+/// in default order: schedule first, then things to act on, then recaps.
+pub fn briefing_engine(workspace: PathBuf, config: &AgentConfig) -> BriefingEngine {
+    let inbox = InboxSection::from_config(&workspace, config);
+    let mut engine = BriefingEngine::new(workspace);
+    engine.register(Arc::new(CalendarSection));
+    engine.register(Arc::new(DeadlinesSection));
+    engine.register(Arc::new(inbox));
+    engine.register(Arc::new(PeopleSection));
+    engine.register(Arc::new(FlashcardsSection));
+    engine.register(Arc::new(FinanceSection));
+    engine.register(Arc::new(CareerSection));
+    engine.register(Arc::new(SystemSection));
+    engine.register(Arc::new(ActivitySection));
+    engine
+}
+
+/// Events from Calendar.app over the briefing window (macOS only).
+pub struct CalendarSection;
+
+#[async_trait]
+impl BriefingSection for CalendarSection {
+    fn id(&self) -> &str {
+        "calendar"
+    }
+
+    fn title(&self) -> &str {
+        "Calendar"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        cfg!(target_os = "macos")
+    }
+
+    async fn render(&self, ctx: &BriefingContext) -> Result<String, String> {
+        #[cfg(target_os = "macos")]
+        {
+            crate::daily_briefing::get_upcoming_events(ctx.period.days()).await
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            let _ = ctx;
+            Err("Calendar.app is only available on macOS".into())
+        }
+    }
+}
+
+/// Flashcards due for review within the briefing window.
+pub struct FlashcardsSection;
+
+#[async_trait]
+impl BriefingSection for FlashcardsSection {
+    fn id(&self) -> &str {
+        "flashcards"
+    }
+
+    fn title(&self) -> &str {
+        "Flashcards"
+    }
+
+    async fn render(&self, ctx: &BriefingContext) -> Result<String, String> {
+        Ok(crate::flashcards::due_cards_briefing(
+            &ctx.workspace,
+            ctx.until(),
+        ))
+    }
+}
+
+/// Life-planner deadlines that are overdue or fall within the window.
+pub struct DeadlinesSection;
+
+#[async_trait]
+impl BriefingSection for DeadlinesSection {
+    fn id(&self) -> &str {
+        "deadlines"
+    }
+
+    fn title(&self) -> &str {
+        "Deadlines"
+    }
+
+    async fn render(&self, ctx: &BriefingContext) -> Result<String, String> {
+        Ok(crate::life_planner::deadlines_briefing(
+            &ctx.workspace,
+            ctx.today,
+            ctx.last_day(),
+        ))
+    }
+}
+
+/// Month-to-date spending and budgets close to their limit.
+pub struct FinanceSection;
+
+#[async_trait]
+impl BriefingSection for FinanceSection {
+    fn id(&self) -> &str {
+        "finance"
+    }
+
+    fn title(&self) -> &str {
+        "Finance"
+    }
+
+    async fn render(&self, ctx: &BriefingContext) -> Result<String, String> {
+        Ok(crate::finance::finance_briefing(&ctx.workspace, ctx.today))
+    }
+}
+
+/// Job applications awaiting follow-up; omitted when there are none.
+pub struct CareerSection;
+
+#[async_trait]
+impl BriefingSection for CareerSection {
+    fn id(&self) -> &str {
+        "career"
+    }
+
+    fn title(&self) -> &str {
+        "Job Search"
+    }
+
+    async fn render(&self, ctx: &BriefingContext) -> Result<String, String> {
+        Ok(crate::career_intel::follow_up_briefing_line(&ctx.workspace).unwrap_or_default())
+    }
+}
+
+/// Generates the daily or week-ahead briefing on any platform.
+pub struct BriefingTool {
+    workspace: PathBuf,
+}
+
+impl BriefingTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
+
+#[async_trait]
+impl Tool for BriefingTool {
+    fn name(&self) -> &str {
+        "briefing"
+    }
+
+    fn description(&self) -> &str {
+        "Generate the user's briefing: calendar, deadlines, inbox digests, due flashcards, \
+         finance, system alerts and recent agent activity, as configured in [briefing]. \
+         Set week_ahead for the next 7 days. Returns markdown and a canvas item."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "week_ahead": {
+                    "type": "boolean",
+                    "description": "Cover the next 7 days instead of today (default: false)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> Result<ToolOutput, ToolError> {
+        let period = if args["week_ahead"].as_bool().unwrap_or(false) {
+            BriefingPeriod::WeekAhead
+        } else {
+            BriefingPeriod::Day
+        };
+        let config =
+            rustant_core::config::load_config(Some(&self.workspace), None).unwrap_or_default();
+        let briefing_config = config.briefing.clone().unwrap_or_default();
+        let engine = briefing_engine(self.workspace.clone(), &config);
+        let briefing = engine
+            .generate(&briefing_config, period, chrono::Utc::now(), None)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed {
+                name: "briefing".into(),
+                message: e.to_string(),
+            })?;
+
+        let target = match &briefing_config.delivery {
+            BriefingDelivery::Canvas {
+                target: Some(target),
+            } => target.clone(),
+            _ => "broadcast".to_string(),
+        };
+        Ok(ToolOutput::text(briefing.to_markdown())
+            .with_artifact(briefing.canvas_artifact(uuid::Uuid::new_v4(), &target)))
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::ReadOnly
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustant_core::BriefingSectionConfig;
+    use rustant_core::types::Artifact;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_briefing_tool_renders_tool_sections_and_canvas_item() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        crate::flashcards::FlashcardsTool::new(workspace.clone())
+            .execute(json!({"action": "add_card", "deck": "rust", "front": "Q", "back": "A"}))
+            .await
+            .unwrap();
+
+        let tool = BriefingTool::new(workspace);
+        let output = tool.execute(json!({"week_ahead": true})).await.unwrap();
+        assert!(output.content.starts_with("# Week Ahead — "));
+        assert!(
+            output
+                .content
+                .contains("## Flashcards\n\n1 card(s) due for review.")
+        );
+        assert!(
+            output
+                .content
+                .contains("## Deadlines\n\nNo deadlines coming up.")
+        );
+        assert!(!output.content.contains("Job Search"));
+        #[cfg(not(target_os = "macos"))]
+        assert!(!output.content.contains("Calendar"));
+        match &output.artifacts[0] {
+            Artifact::CanvasItem {
+                target,
+                content_type,
+                content,
+                ..
+            } => {
+                assert_eq!(target, "broadcast");
+                assert_eq!(content_type, "markdown");
+                assert_eq!(content, &output.content);
+            }
+            other => panic!("unexpected artifact {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_calendar_section_unavailable_off_macos() {
+        let engine = briefing_engine(std::env::temp_dir(), &AgentConfig::default());
+        let config = rustant_core::BriefingConfig {
+            sections: vec![BriefingSectionConfig::new("calendar")],
+            ..Default::default()
+        };
+        let briefing = engine
+            .generate(&config, BriefingPeriod::Day, chrono::Utc::now(), None)
+            .await
+            .unwrap();
+        #[cfg(not(target_os = "macos"))]
+        assert!(
+            briefing
+                .to_markdown()
+                .contains("_Calendar unavailable: Calendar.app is only available on macOS_")
+        );
+        assert_eq!(
+            engine.section_ids(),
+            vec![
+                "calendar",
+                "deadlines",
+                "inbox",
+                "people",
+                "flashcards",
+                "finance",
+                "career",
+                "system",
+                "activity"
+            ]
+        );
+    }
+}
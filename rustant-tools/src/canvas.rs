@@ -1,11 +1,15 @@
 //! Canvas tools — Agent-callable tools for pushing content to the canvas UI.
 //!
 //! Provides 5 tools: canvas_push, canvas_clear, canvas_update, canvas_snapshot, canvas_interact.
+//! Gateway tasks also get wait_for_form, which asks the user for input
+//! through a dashboard form.
 
 use async_trait::async_trait;
+use rustant_core::canvas::FormSpec;
 use rustant_core::canvas::{CanvasManager, CanvasMessage, CanvasTarget, ContentType};
 use rustant_core::error::ToolError;
-use rustant_core::types::{RiskLevel, ToolOutput};
+use rustant_core::gateway::{FormOutcome, TaskProgress};
+use rustant_core::types::{Artifact, RiskLevel, ToolOutput};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
@@ -98,10 +102,16 @@ impl Tool for CanvasPushTool {
             }
         })?;
 
-        Ok(ToolOutput::text(format!(
-            "Content pushed to canvas (id: {})",
-            id
-        )))
+        Ok(
+            ToolOutput::text(format!("Content pushed to canvas (id: {})", id)).with_artifact(
+                Artifact::CanvasItem {
+                    id: id.to_string(),
+                    target: args["target"].as_str().unwrap_or("").to_string(),
+                    content_type: content_type_str.to_lowercase(),
+                    content: content.to_string(),
+                },
+            ),
+        )
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -408,6 +418,127 @@ impl Tool for CanvasInteractTool {
     }
 }
 
+// --- wait_for_form ---
+
+/// Longest a task waits on one form; forms without a shorter expiry get it.
+const FORM_WAIT_LIMIT_SECS: u64 = 3600;
+
+/// Tool to show a form on the dashboard and wait for the user's submission.
+pub struct WaitForFormTool {
+    progress: TaskProgress,
+}
+
+impl WaitForFormTool {
+    pub fn new(progress: TaskProgress) -> Self {
+        Self { progress }
+    }
+}
+
+#[async_trait]
+impl Tool for WaitForFormTool {
+    fn name(&self) -> &str {
+        "wait_for_form"
+    }
+
+    fn description(&self) -> &str {
+        "Show a form on the dashboard and wait until the user submits it. Returns the \
+         submitted values as JSON, validated against the field definitions. Mark secrets \
+         as sensitive so they are typed blind, never echoed to other clients and redacted \
+         from the gateway's record of the submission."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "title": {
+                    "type": "string",
+                    "description": "Form title"
+                },
+                "fields": {
+                    "type": "array",
+                    "description": "Fields to ask for",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string", "description": "Key of the value in the result" },
+                            "label": { "type": "string" },
+                            "field_type": {
+                                "type": "string",
+                                "enum": ["text", "number", "email", "select", "textarea", "checkbox"]
+                            },
+                            "required": { "type": "boolean" },
+                            "options": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Choices for select fields"
+                            },
+                            "placeholder": { "type": "string" },
+                            "default_value": { "type": "string" },
+                            "sensitive": {
+                                "type": "boolean",
+                                "description": "Password or token; never echoed or recorded"
+                            }
+                        },
+                        "required": ["name", "label", "field_type"]
+                    }
+                },
+                "submit_text": {
+                    "type": "string",
+                    "description": "Submit button text (default: Submit)"
+                },
+                "expires_in_secs": {
+                    "type": "integer",
+                    "description": "Seconds to wait for a submission (default and maximum: 3600)"
+                }
+            },
+            "required": ["fields"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> Result<ToolOutput, ToolError> {
+        let mut spec: FormSpec =
+            serde_json::from_value(args).map_err(|e| ToolError::InvalidArguments {
+                name: "wait_for_form".into(),
+                reason: format!("invalid form: {}", e),
+            })?;
+        let problems = spec.problems();
+        if !problems.is_empty() {
+            return Err(ToolError::InvalidArguments {
+                name: "wait_for_form".into(),
+                reason: problems.join("; "),
+            });
+        }
+        let wait_secs = spec
+            .expires_in_secs
+            .unwrap_or(FORM_WAIT_LIMIT_SECS)
+            .min(FORM_WAIT_LIMIT_SECS);
+        spec.expires_in_secs = Some(wait_secs);
+        // Templates are for forms nobody waits on.
+        spec.on_submit = None;
+
+        match self.progress.wait_for_form(spec).await {
+            FormOutcome::Submitted { values, .. } => Ok(ToolOutput::text(format!(
+                "Form submitted:\n{}",
+                serde_json::to_string_pretty(&values).unwrap_or_default()
+            ))),
+            FormOutcome::Expired => Err(ToolError::Timeout {
+                name: "wait_for_form".into(),
+                timeout_secs: wait_secs,
+            }),
+        }
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::ReadOnly
+    }
+
+    fn timeout(&self) -> Duration {
+        // The form's own expiry resolves the wait first.
+        Duration::from_secs(FORM_WAIT_LIMIT_SECS + 30)
+    }
+}
+
 /// Register all canvas tools into a ToolRegistry.
 pub fn register_canvas_tools(registry: &mut crate::registry::ToolRegistry, canvas: SharedCanvas) {
     let tools: Vec<Arc<dyn Tool>> = vec![
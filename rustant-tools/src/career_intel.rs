@@ -1,13 +1,21 @@
 //! Career intelligence tool — goal tracking, achievements, portfolio, networking, and strategy.
+//!
+//! Also covers the job search workflow: job postings are parsed into structured
+//! fields, compared against the stored skill profile and workspace resume, and
+//! tracked as applications with follow-up reminders registered through the
+//! channel [`SchedulerBridge`]. All data stays in `.rustant/career/intel.json`.
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use rustant_core::channels::SchedulerBridge;
+use rustant_core::config::MessagePriority;
 use rustant_core::error::ToolError;
 use rustant_core::types::{RiskLevel, ToolOutput};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::time::Duration;
+use uuid::Uuid;
 
 use crate::registry::Tool;
 
@@ -161,15 +169,447 @@ struct NetworkNote {
     follow_up: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JobPosting {
+    id: usize,
+    title: String,
+    company: String,
+    location: Option<String>,
+    url: Option<String>,
+    required_skills: Vec<String>,
+    nice_to_have: Vec<String>,
+    /// Requirement bullets as written in the posting.
+    requirements: Vec<String>,
+    compensation: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+enum ApplicationStatus {
+    Saved,
+    Applied,
+    Interviewing,
+    Offer,
+    Rejected,
+}
+
+impl std::fmt::Display for ApplicationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApplicationStatus::Saved => write!(f, "Saved"),
+            ApplicationStatus::Applied => write!(f, "Applied"),
+            ApplicationStatus::Interviewing => write!(f, "Interviewing"),
+            ApplicationStatus::Offer => write!(f, "Offer"),
+            ApplicationStatus::Rejected => write!(f, "Rejected"),
+        }
+    }
+}
+
+impl ApplicationStatus {
+    /// Whether the application state machine allows moving to `next`.
+    fn can_transition_to(self, next: ApplicationStatus) -> bool {
+        use ApplicationStatus::*;
+        matches!(
+            (self, next),
+            (Saved, Applied)
+                | (Saved, Rejected)
+                | (Applied, Interviewing)
+                | (Applied, Rejected)
+                | (Interviewing, Offer)
+                | (Interviewing, Rejected)
+        )
+    }
+
+    /// Whether the application is still open (not offer or rejected).
+    fn is_active(self) -> bool {
+        !matches!(self, ApplicationStatus::Offer | ApplicationStatus::Rejected)
+    }
+}
+
+fn parse_application_status(s: &str) -> Option<ApplicationStatus> {
+    match s.to_lowercase().as_str() {
+        "saved" => Some(ApplicationStatus::Saved),
+        "applied" => Some(ApplicationStatus::Applied),
+        "interviewing" | "interview" => Some(ApplicationStatus::Interviewing),
+        "offer" => Some(ApplicationStatus::Offer),
+        "rejected" => Some(ApplicationStatus::Rejected),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ApplicationEvent {
+    status: ApplicationStatus,
+    date: String,
+    note: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JobApplication {
+    id: usize,
+    posting_id: Option<usize>,
+    title: String,
+    company: String,
+    status: ApplicationStatus,
+    history: Vec<ApplicationEvent>,
+    /// Follow-up date YYYY-MM-DD, if one is scheduled.
+    follow_up_date: Option<String>,
+    /// Reminder registered with the scheduler bridge for the follow-up.
+    reminder_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+impl JobApplication {
+    fn needs_follow_up(&self, today: NaiveDate) -> bool {
+        self.status.is_active()
+            && self
+                .follow_up_date
+                .as_deref()
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                .is_some_and(|d| d <= today)
+    }
+}
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 struct CareerState {
     goals: Vec<CareerGoal>,
     achievements: Vec<Achievement>,
     portfolio: Vec<PortfolioItem>,
     network_notes: Vec<NetworkNote>,
+    #[serde(default)]
+    postings: Vec<JobPosting>,
+    #[serde(default)]
+    applications: Vec<JobApplication>,
     next_id: usize,
 }
 
+// ---------------------------------------------------------------------------
+// Job posting parsing
+// ---------------------------------------------------------------------------
+
+/// Skills recognised in postings in addition to the user's own tracked skills.
+const SKILL_VOCABULARY: &[&str] = &[
+    "rust",
+    "python",
+    "java",
+    "javascript",
+    "typescript",
+    "golang",
+    "c++",
+    "c#",
+    "ruby",
+    "kotlin",
+    "swift",
+    "scala",
+    "sql",
+    "postgresql",
+    "mysql",
+    "mongodb",
+    "redis",
+    "kafka",
+    "graphql",
+    "rest",
+    "grpc",
+    "react",
+    "vue",
+    "angular",
+    "node.js",
+    "django",
+    "flask",
+    "spring",
+    "aws",
+    "gcp",
+    "azure",
+    "docker",
+    "kubernetes",
+    "terraform",
+    "ansible",
+    "linux",
+    "ci/cd",
+    "git",
+    "machine learning",
+    "deep learning",
+    "pytorch",
+    "tensorflow",
+    "nlp",
+    "llm",
+    "data engineering",
+    "spark",
+    "airflow",
+    "distributed systems",
+    "microservices",
+    "system design",
+    "security",
+    "observability",
+    "prometheus",
+    "leadership",
+    "mentoring",
+    "communication",
+    "agile",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PostingSection {
+    Preamble,
+    Required,
+    NiceToHave,
+    Other,
+}
+
+/// Case-insensitive whole-term search (`java` does not match `javascript`).
+fn contains_term(haystack_lower: &str, term: &str) -> bool {
+    let term = term.to_lowercase();
+    if term.is_empty() {
+        return false;
+    }
+    let is_word = |c: char| c.is_alphanumeric();
+    let mut start = 0;
+    while let Some(pos) = haystack_lower[start..].find(&term) {
+        let begin = start + pos;
+        let end = begin + term.len();
+        let before_ok = haystack_lower[..begin]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !is_word(c));
+        let after_ok = haystack_lower[end..]
+            .chars()
+            .next()
+            .is_none_or(|c| !is_word(c));
+        if before_ok && after_ok {
+            return true;
+        }
+        start = begin
+            + haystack_lower[begin..]
+                .chars()
+                .next()
+                .map_or(1, |c| c.len_utf8());
+    }
+    false
+}
+
+fn strip_bullet(line: &str) -> &str {
+    let trimmed = line.trim();
+    let trimmed = trimmed
+        .trim_start_matches(['-', '*', '•', '·'])
+        .trim_start();
+    // Numbered bullets: "1." / "2)"
+    match trimmed.find(['.', ')']) {
+        Some(idx) if idx > 0 && idx <= 2 && trimmed[..idx].chars().all(|c| c.is_ascii_digit()) => {
+            trimmed[idx + 1..].trim_start()
+        }
+        _ => trimmed,
+    }
+}
+
+fn classify_header(line: &str) -> Option<PostingSection> {
+    let lower = line.to_lowercase();
+    let looks_like_header = line.len() <= 60 && (line.ends_with(':') || !line.contains('.'));
+    if !looks_like_header {
+        return None;
+    }
+    if [
+        "nice to have",
+        "nice-to-have",
+        "preferred",
+        "bonus",
+        "plus if",
+        "good to have",
+    ]
+    .iter()
+    .any(|k| lower.contains(k))
+    {
+        return Some(PostingSection::NiceToHave);
+    }
+    if [
+        "requirement",
+        "qualification",
+        "must have",
+        "must-have",
+        "what you'll need",
+        "what you need",
+        "what you bring",
+        "you have",
+        "skills",
+    ]
+    .iter()
+    .any(|k| lower.contains(k))
+    {
+        return Some(PostingSection::Required);
+    }
+    if line.ends_with(':') {
+        return Some(PostingSection::Other);
+    }
+    None
+}
+
+fn labelled_value<'a>(line: &'a str, labels: &[&str]) -> Option<&'a str> {
+    let lower = line.to_lowercase();
+    labels.iter().find_map(|label| {
+        lower
+            .strip_prefix(label)
+            .and_then(|rest| rest.trim_start().strip_prefix(':'))
+            .map(|_| line[label.len()..].trim_start()[1..].trim())
+            .filter(|v| !v.is_empty())
+    })
+}
+
+fn find_compensation(text: &str) -> Option<String> {
+    text.lines().map(str::trim).find_map(|line| {
+        let lower = line.to_lowercase();
+        let has_currency =
+            line.contains(['$', '€', '£']) && line.chars().any(|c| c.is_ascii_digit());
+        let has_keyword = ["salary", "compensation", "pay range", "base pay"]
+            .iter()
+            .any(|k| lower.contains(k))
+            && line.chars().any(|c| c.is_ascii_digit());
+        (has_currency || has_keyword).then(|| strip_bullet(line).chars().take(160).collect())
+    })
+}
+
+/// Parse free-form posting text into structured fields.
+///
+/// `known_skills` (typically the user's tracked skills) are matched alongside
+/// the built-in vocabulary so that profile skills are never missed.
+fn parse_posting_text(text: &str, known_skills: &[String]) -> JobPosting {
+    let mut title = String::new();
+    let mut company = String::new();
+    let mut location = None;
+    let mut required_lines = Vec::new();
+    let mut nice_lines = Vec::new();
+    let mut section = PostingSection::Preamble;
+    let mut saw_sections = false;
+
+    for raw in text.lines() {
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(v) = labelled_value(line, &["job title", "title", "position", "role"]) {
+            title = v.to_string();
+            continue;
+        }
+        if let Some(v) = labelled_value(line, &["company", "employer", "organization"]) {
+            company = v.to_string();
+            continue;
+        }
+        if let Some(v) = labelled_value(line, &["location"]) {
+            location = Some(v.to_string());
+            continue;
+        }
+        let is_bullet = strip_bullet(line) != line;
+        if !is_bullet && let Some(next) = classify_header(line) {
+            section = next;
+            saw_sections |= next != PostingSection::Other;
+            continue;
+        }
+        match section {
+            PostingSection::Required => required_lines.push(strip_bullet(line).to_string()),
+            PostingSection::NiceToHave => nice_lines.push(strip_bullet(line).to_string()),
+            PostingSection::Preamble if title.is_empty() => {
+                // "Senior Engineer at Acme" / "Senior Engineer - Acme"
+                let (t, c) = line
+                    .split_once(" at ")
+                    .or_else(|| line.split_once(" - "))
+                    .or_else(|| line.split_once(" | "))
+                    .unwrap_or((line, ""));
+                title = t.trim().chars().take(100).collect();
+                if company.is_empty() && !c.trim().is_empty() {
+                    company = c.trim().chars().take(100).collect();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Without recognisable sections, treat the whole posting as requirements.
+    if !saw_sections {
+        required_lines = text
+            .lines()
+            .map(strip_bullet)
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+            .collect();
+    }
+
+    let vocabulary = SKILL_VOCABULARY
+        .iter()
+        .map(|s| s.to_string())
+        .chain(known_skills.iter().cloned());
+    let required_text = required_lines.join("\n").to_lowercase();
+    let nice_text = nice_lines.join("\n").to_lowercase();
+    let mut required_skills: Vec<String> = Vec::new();
+    let mut nice_to_have: Vec<String> = Vec::new();
+    for skill in vocabulary {
+        let key = skill.to_lowercase();
+        if required_skills
+            .iter()
+            .chain(&nice_to_have)
+            .any(|s| s.to_lowercase() == key)
+        {
+            continue;
+        }
+        if contains_term(&required_text, &key) {
+            required_skills.push(skill);
+        } else if contains_term(&nice_text, &key) {
+            nice_to_have.push(skill);
+        }
+    }
+
+    JobPosting {
+        id: 0,
+        title: if title.is_empty() {
+            "Untitled role".into()
+        } else {
+            title
+        },
+        company: if company.is_empty() {
+            "Unknown company".into()
+        } else {
+            company
+        },
+        location,
+        url: None,
+        required_skills,
+        nice_to_have,
+        requirements: required_lines.into_iter().take(30).collect(),
+        compensation: find_compensation(text),
+        created_at: Utc::now(),
+    }
+}
+
+/// Applications whose follow-up date has arrived, as `(title, company)` pairs.
+pub fn applications_needing_follow_up(workspace: &Path) -> Vec<(String, String)> {
+    let tool = CareerIntelTool::new(workspace.to_path_buf());
+    let today = Utc::now().date_naive();
+    tool.load_state()
+        .applications
+        .iter()
+        .filter(|a| a.needs_follow_up(today))
+        .map(|a| (a.title.clone(), a.company.clone()))
+        .collect()
+}
+
+/// One-line summary for the daily briefing, e.g. "2 applications need follow-up".
+pub fn follow_up_briefing_line(workspace: &Path) -> Option<String> {
+    let pending = applications_needing_follow_up(workspace);
+    match pending.len() {
+        0 => None,
+        1 => Some(format!(
+            "1 application needs follow-up: {} at {}",
+            pending[0].0, pending[0].1
+        )),
+        n => Some(format!(
+            "{} applications need follow-up: {}",
+            n,
+            pending
+                .iter()
+                .map(|(t, c)| format!("{} at {}", t, c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tool
 // ---------------------------------------------------------------------------
@@ -203,6 +643,8 @@ impl CareerIntelTool {
                 achievements: Vec::new(),
                 portfolio: Vec::new(),
                 network_notes: Vec::new(),
+                postings: Vec::new(),
+                applications: Vec::new(),
                 next_id: 1,
             }
         }
@@ -804,6 +1246,550 @@ impl CareerIntelTool {
 
         Ok(ToolOutput::text(prompt))
     }
+
+    // ------------------------------------------------------------------
+    // Job search
+    // ------------------------------------------------------------------
+
+    /// Tracked skills as `(name, proficiency_level)` pairs from the skill tracker.
+    fn tracked_skills(&self) -> Vec<(String, u64)> {
+        self.load_skills_data()
+            .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+            .and_then(|v| v.get("skills").and_then(|s| s.as_array()).cloned())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|s| {
+                let name = s.get("name").and_then(|n| n.as_str())?;
+                let level = s
+                    .get("proficiency_level")
+                    .and_then(|l| l.as_u64())
+                    .unwrap_or(0);
+                Some((name.to_string(), level))
+            })
+            .collect()
+    }
+
+    /// Load the resume from `resume_path` (workspace-relative) or the default locations.
+    fn load_resume(&self, resume_path: Option<&str>) -> Result<Option<(String, String)>, String> {
+        if let Some(rel) = resume_path {
+            let rel_path = Path::new(rel);
+            if rel_path
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+            {
+                return Err(format!(
+                    "resume_path '{}' must be relative to the workspace.",
+                    rel
+                ));
+            }
+            let path = self.workspace.join(rel_path);
+            return std::fs::read_to_string(&path)
+                .map(|text| Some((rel.to_string(), text)))
+                .map_err(|e| format!("Could not read resume '{}': {}", rel, e));
+        }
+        const DEFAULTS: &[&str] = &[
+            "resume.md",
+            "resume.txt",
+            "cv.md",
+            "cv.txt",
+            ".rustant/career/resume.md",
+        ];
+        Ok(DEFAULTS.iter().find_map(|rel| {
+            std::fs::read_to_string(self.workspace.join(rel))
+                .ok()
+                .map(|text| (rel.to_string(), text))
+        }))
+    }
+
+    async fn parse_posting(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let url = args.get("url").and_then(|v| v.as_str()).unwrap_or("");
+        let text = if let Some(text) = args.get("text").and_then(|v| v.as_str()) {
+            text.to_string()
+        } else if !url.is_empty() {
+            let fetched = crate::web::WebFetchTool::new()
+                .execute(json!({ "url": url, "max_length": 20000 }))
+                .await?;
+            match fetched.content.split_once("\n\n") {
+                Some((header, body)) if header.starts_with("Content from") => body.to_string(),
+                _ => return Ok(ToolOutput::text(fetched.content)),
+            }
+        } else {
+            return Ok(ToolOutput::text(
+                "Provide the posting as 'text' or a 'url' to fetch.",
+            ));
+        };
+        if text.trim().is_empty() {
+            return Ok(ToolOutput::text("The posting text is empty."));
+        }
+
+        let known: Vec<String> = self.tracked_skills().into_iter().map(|(n, _)| n).collect();
+        let mut posting = parse_posting_text(&text, &known);
+        if let Some(title) = args.get("title").and_then(|v| v.as_str()) {
+            posting.title = title.to_string();
+        }
+        if let Some(company) = args.get("company").and_then(|v| v.as_str()) {
+            posting.company = company.to_string();
+        }
+        if !url.is_empty() {
+            posting.url = Some(url.to_string());
+        }
+
+        let mut state = self.load_state();
+        posting.id = state.next_id;
+        state.next_id += 1;
+
+        let mut out = format!(
+            "Posting #{} saved: {} at {}\n",
+            posting.id, posting.title, posting.company
+        );
+        if let Some(ref loc) = posting.location {
+            out.push_str(&format!("  Location: {}\n", loc));
+        }
+        if let Some(ref comp) = posting.compensation {
+            out.push_str(&format!("  Compensation: {}\n", comp));
+        }
+        out.push_str(&format!(
+            "  Required skills: {}\n",
+            if posting.required_skills.is_empty() {
+                "(none detected)".to_string()
+            } else {
+                posting.required_skills.join(", ")
+            }
+        ));
+        if !posting.nice_to_have.is_empty() {
+            out.push_str(&format!(
+                "  Nice to have: {}\n",
+                posting.nice_to_have.join(", ")
+            ));
+        }
+        out.push_str(&format!(
+            "Run gap_analysis with posting_id {} to compare against your profile.",
+            posting.id
+        ));
+
+        state.postings.push(posting);
+        self.save_state(&state)?;
+        Ok(ToolOutput::text(out))
+    }
+
+    fn posting_gap_analysis(
+        &self,
+        args: &Value,
+        posting_id: usize,
+    ) -> Result<ToolOutput, ToolError> {
+        let state = self.load_state();
+        let Some(posting) = state.postings.iter().find(|p| p.id == posting_id) else {
+            return Ok(ToolOutput::text(format!(
+                "Posting #{} not found.",
+                posting_id
+            )));
+        };
+        let resume = match self.load_resume(args.get("resume_path").and_then(|v| v.as_str())) {
+            Ok(r) => r,
+            Err(msg) => return Ok(ToolOutput::text(msg)),
+        };
+        let resume_lower = resume
+            .as_ref()
+            .map(|(_, t)| t.to_lowercase())
+            .unwrap_or_default();
+        let tracked = self.tracked_skills();
+
+        let has_skill = |skill: &str| {
+            contains_term(&resume_lower, skill)
+                || tracked
+                    .iter()
+                    .any(|(name, level)| name.eq_ignore_ascii_case(skill) && *level > 0)
+        };
+        let matched: Vec<&String> = posting
+            .required_skills
+            .iter()
+            .chain(&posting.nice_to_have)
+            .filter(|s| has_skill(s))
+            .collect();
+        let missing_required: Vec<&String> = posting
+            .required_skills
+            .iter()
+            .filter(|s| !has_skill(s))
+            .collect();
+        let missing_nice: Vec<&String> = posting
+            .nice_to_have
+            .iter()
+            .filter(|s| !has_skill(s))
+            .collect();
+
+        let mut suggestions = Vec::new();
+        // Tracked skills the posting wants that the resume never mentions.
+        for skill in posting.required_skills.iter().chain(&posting.nice_to_have) {
+            if resume.is_some()
+                && !contains_term(&resume_lower, skill)
+                && tracked
+                    .iter()
+                    .any(|(n, l)| n.eq_ignore_ascii_case(skill) && *l > 0)
+            {
+                suggestions.push(format!(
+                    "Add a bullet showing your {} experience — it is in your skill profile but not on your resume.",
+                    skill
+                ));
+            }
+        }
+        // Resume bullets that touch a required skill but lack a measurable result.
+        if let Some((_, ref text)) = resume {
+            for line in text.lines() {
+                let bullet = strip_bullet(line);
+                if bullet == line.trim() || bullet.chars().any(|c| c.is_ascii_digit()) {
+                    continue;
+                }
+                let lower = bullet.to_lowercase();
+                if let Some(skill) = posting
+                    .required_skills
+                    .iter()
+                    .find(|s| contains_term(&lower, s))
+                {
+                    suggestions.push(format!(
+                        "Quantify \"{}\" (scale, latency, cost, users) to strengthen the {} match.",
+                        bullet, skill
+                    ));
+                }
+            }
+        }
+        for skill in &missing_required {
+            match state.portfolio.iter().find(|p| {
+                p.skills_demonstrated
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(skill))
+            }) {
+                Some(item) => suggestions.push(format!(
+                    "Mention portfolio item '{}' to evidence {}.",
+                    item.title, skill
+                )),
+                None => suggestions.push(format!(
+                    "No evidence of {} yet — consider a set_goal to build it.",
+                    skill
+                )),
+            }
+        }
+
+        let join = |v: &[&String]| {
+            if v.is_empty() {
+                "(none)".to_string()
+            } else {
+                v.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")
+            }
+        };
+        let mut out = format!(
+            "Gap analysis for #{} {} at {}\n",
+            posting.id, posting.title, posting.company
+        );
+        out.push_str(&format!(
+            "  Resume: {}\n",
+            resume.as_ref().map_or("(not found)", |(p, _)| p.as_str())
+        ));
+        out.push_str(&format!("  Matched: {}\n", join(&matched)));
+        out.push_str(&format!(
+            "  Missing (required): {}\n",
+            join(&missing_required)
+        ));
+        out.push_str(&format!(
+            "  Missing (nice to have): {}\n",
+            join(&missing_nice)
+        ));
+        if !suggestions.is_empty() {
+            out.push_str("\nSuggested resume edits:\n");
+            for s in &suggestions {
+                out.push_str(&format!("- {}\n", s));
+            }
+        }
+
+        let mut output = ToolOutput::text(out);
+        output.metadata.insert(
+            "gap_analysis".into(),
+            json!({
+                "posting_id": posting.id,
+                "matched": matched,
+                "missing_required": missing_required,
+                "missing_nice_to_have": missing_nice,
+                "suggestions": suggestions,
+            }),
+        );
+        Ok(output)
+    }
+
+    fn track_application(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let mut state = self.load_state();
+        let posting_id = args
+            .get("posting_id")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize);
+        let posting = match posting_id {
+            Some(pid) => match state.postings.iter().find(|p| p.id == pid) {
+                Some(p) => Some(p.clone()),
+                None => return Ok(ToolOutput::text(format!("Posting #{} not found.", pid))),
+            },
+            None => None,
+        };
+        let title = args
+            .get("title")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| posting.as_ref().map(|p| p.title.clone()));
+        let company = args
+            .get("company")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| posting.as_ref().map(|p| p.company.clone()));
+        let (Some(title), Some(company)) = (title, company) else {
+            return Ok(ToolOutput::text(
+                "Provide a posting_id or both title and company for the application.",
+            ));
+        };
+        let status = match args.get("status").and_then(|v| v.as_str()) {
+            None => ApplicationStatus::Saved,
+            Some(s) => match parse_application_status(s) {
+                Some(st @ (ApplicationStatus::Saved | ApplicationStatus::Applied)) => st,
+                _ => {
+                    return Ok(ToolOutput::text(format!(
+                        "New applications start as saved or applied, not '{}'.",
+                        s
+                    )));
+                }
+            },
+        };
+        let note = args
+            .get("notes")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+
+        let id = state.next_id;
+        state.next_id += 1;
+        let mut app = JobApplication {
+            id,
+            posting_id,
+            title,
+            company,
+            status,
+            history: vec![ApplicationEvent {
+                status,
+                date: args
+                    .get("date")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(Self::today_str),
+                note,
+            }],
+            follow_up_date: None,
+            reminder_id: None,
+            created_at: Utc::now(),
+        };
+        let reminder = self.apply_follow_up(&mut app, args)?;
+        let mut out = format!(
+            "Application #{} tracked: {} at {} [{}]",
+            id, app.title, app.company, app.status
+        );
+        if let Some(ref date) = reminder {
+            out.push_str(&format!(" — follow-up on {}", date));
+        }
+        state.applications.push(app);
+        self.save_state(&state)?;
+        Ok(ToolOutput::text(out))
+    }
+
+    fn update_application(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let Some(app_id) = args
+            .get("application_id")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+        else {
+            return Ok(ToolOutput::text("Provide an application_id to update."));
+        };
+        let mut state = self.load_state();
+        let Some(idx) = state.applications.iter().position(|a| a.id == app_id) else {
+            return Ok(ToolOutput::text(format!(
+                "Application #{} not found.",
+                app_id
+            )));
+        };
+        let note = args.get("notes").and_then(|v| v.as_str()).unwrap_or("");
+        let date = args
+            .get("date")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(Self::today_str);
+
+        let mut app = state.applications[idx].clone();
+        let mut out = String::new();
+        if let Some(s) = args.get("status").and_then(|v| v.as_str()) {
+            let Some(next) = parse_application_status(s) else {
+                return Ok(ToolOutput::text(format!(
+                    "Unknown status '{}'. Use: saved, applied, interviewing, offer, rejected.",
+                    s
+                )));
+            };
+            if !app.status.can_transition_to(next) {
+                return Ok(ToolOutput::text(format!(
+                    "Cannot move application #{} from {} to {}.",
+                    app_id, app.status, next
+                )));
+            }
+            out.push_str(&format!(
+                "Application #{} moved {} → {}",
+                app_id, app.status, next
+            ));
+            app.status = next;
+            app.history.push(ApplicationEvent {
+                status: next,
+                date,
+                note: note.to_string(),
+            });
+        } else if !note.is_empty() {
+            out.push_str(&format!("Note added to application #{}", app_id));
+            app.history.push(ApplicationEvent {
+                status: app.status,
+                date,
+                note: note.to_string(),
+            });
+        } else if args.get("follow_up_days").is_none() && args.get("follow_up_date").is_none() {
+            return Ok(ToolOutput::text(
+                "Provide a status, notes, or follow-up to update the application.",
+            ));
+        } else {
+            out.push_str(&format!("Application #{} updated", app_id));
+        }
+
+        if let Some(date) = self.apply_follow_up(&mut app, args)? {
+            out.push_str(&format!(" — follow-up on {}", date));
+        }
+        state.applications[idx] = app;
+        self.save_state(&state)?;
+        Ok(ToolOutput::text(out))
+    }
+
+    /// Reconcile the follow-up date and scheduler reminder after a change.
+    ///
+    /// Moving to Applied defaults to a follow-up a week out; explicit
+    /// `follow_up_days` / `follow_up_date` override it. Terminal states clear
+    /// the follow-up. Returns the newly scheduled date, if any.
+    fn apply_follow_up(
+        &self,
+        app: &mut JobApplication,
+        args: &Value,
+    ) -> Result<Option<String>, ToolError> {
+        let today = Utc::now().date_naive();
+        let explicit = if let Some(d) = args.get("follow_up_date").and_then(|v| v.as_str()) {
+            Some(NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| {
+                ToolError::InvalidArguments {
+                    name: "career_intel".to_string(),
+                    reason: format!("follow_up_date '{}' must be YYYY-MM-DD", d),
+                }
+            })?)
+        } else {
+            args.get("follow_up_days")
+                .and_then(|v| v.as_u64())
+                .map(|days| today + chrono::Duration::days(days as i64))
+        };
+        let just_applied = app.status == ApplicationStatus::Applied
+            && app
+                .history
+                .last()
+                .is_some_and(|e| e.status == ApplicationStatus::Applied)
+            && args.get("status").is_some();
+        let target = match explicit {
+            Some(d) if app.status.is_active() => Some(d),
+            _ if just_applied => Some(today + chrono::Duration::days(7)),
+            _ => None,
+        };
+        let clear = !app.status.is_active();
+        if target.is_none() && !clear {
+            return Ok(None);
+        }
+
+        let mut bridge = SchedulerBridge::new(self.workspace.join(".rustant").join("reminders"));
+        let io_err = |e: std::io::Error| ToolError::ExecutionFailed {
+            name: "career_intel".to_string(),
+            message: format!("Failed to update follow-up reminders: {}", e),
+        };
+        bridge.load_index().map_err(io_err)?;
+        if let Some(old) = app.reminder_id.take() {
+            bridge.complete(old);
+        }
+        app.follow_up_date = None;
+
+        let scheduled = if let Some(date) = target {
+            let remind_at = date
+                .and_hms_opt(9, 0, 0)
+                .map(|dt| dt.and_utc())
+                .unwrap_or_else(Utc::now);
+            let minutes = (remind_at - Utc::now()).num_minutes().max(0);
+            let reminder = bridge.schedule_followup(
+                &format!("Follow up on {} application at {}", app.title, app.company),
+                "career",
+                &app.company,
+                u32::try_from(minutes).unwrap_or(u32::MAX),
+                MessagePriority::Normal,
+            );
+            bridge.export_ics(&reminder).map_err(io_err)?;
+            app.reminder_id = Some(reminder.id);
+            app.follow_up_date = Some(date.format("%Y-%m-%d").to_string());
+            app.follow_up_date.clone()
+        } else {
+            None
+        };
+        bridge.save_index().map_err(io_err)?;
+        Ok(scheduled)
+    }
+
+    fn list_applications(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let state = self.load_state();
+        let status_filter = match args.get("status").and_then(|v| v.as_str()) {
+            Some(s) => match parse_application_status(s) {
+                Some(st) => Some(st),
+                None => {
+                    return Ok(ToolOutput::text(format!(
+                        "Unknown status '{}'. Use: saved, applied, interviewing, offer, rejected.",
+                        s
+                    )));
+                }
+            },
+            None => None,
+        };
+        let follow_up_only = args
+            .get("needs_follow_up")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let today = Utc::now().date_naive();
+
+        let apps: Vec<&JobApplication> = state
+            .applications
+            .iter()
+            .filter(|a| status_filter.is_none_or(|s| a.status == s))
+            .filter(|a| !follow_up_only || a.needs_follow_up(today))
+            .collect();
+        if apps.is_empty() {
+            return Ok(ToolOutput::text("No matching applications."));
+        }
+
+        let mut out = format!("Applications ({}):\n", apps.len());
+        for a in &apps {
+            let since = a.history.last().map(|e| e.date.as_str()).unwrap_or("");
+            out.push_str(&format!(
+                "  #{} [{}] {} at {} (since {})",
+                a.id, a.status, a.title, a.company, since
+            ));
+            if let Some(ref d) = a.follow_up_date {
+                let due = if a.needs_follow_up(today) {
+                    " — due"
+                } else {
+                    ""
+                };
+                out.push_str(&format!(" follow-up {}{}", d, due));
+            }
+            out.push('\n');
+            if let Some(note) = a.history.iter().rev().find(|e| !e.note.is_empty()) {
+                out.push_str(&format!("      {}: {}\n", note.date, note.note));
+            }
+        }
+        Ok(ToolOutput::text(out))
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -817,7 +1803,7 @@ impl Tool for CareerIntelTool {
     }
 
     fn description(&self) -> &str {
-        "Career strategy intelligence: goal tracking, achievements, portfolio, networking. Actions: set_goal, log_achievement, add_portfolio, gap_analysis, market_scan, network_note, progress_report, strategy_review."
+        "Career strategy intelligence: goal tracking, achievements, portfolio, networking, job postings and application tracking. Actions: set_goal, log_achievement, add_portfolio, gap_analysis, market_scan, network_note, progress_report, strategy_review, parse_posting, track_application, update_application, list_applications."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -826,7 +1812,7 @@ impl Tool for CareerIntelTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["set_goal", "log_achievement", "add_portfolio", "gap_analysis", "market_scan", "network_note", "progress_report", "strategy_review"],
+                    "enum": ["set_goal", "log_achievement", "add_portfolio", "gap_analysis", "market_scan", "network_note", "progress_report", "strategy_review", "parse_posting", "track_application", "update_application", "list_applications"],
                     "description": "Action to perform"
                 },
                 "title": { "type": "string", "description": "Title (for goal, achievement, or portfolio item)" },
@@ -860,7 +1846,7 @@ impl Tool for CareerIntelTool {
                     "enum": ["project", "paper", "talk", "blog", "certification", "open_source"],
                     "description": "Portfolio item type"
                 },
-                "url": { "type": "string", "description": "URL (for portfolio items)" },
+                "url": { "type": "string", "description": "URL (for portfolio items, or a job posting to fetch)" },
                 "skills_demonstrated": {
                     "type": "array",
                     "items": { "type": "string" },
@@ -873,7 +1859,20 @@ impl Tool for CareerIntelTool {
                 "follow_up": { "type": "string", "description": "Follow-up action (for network notes)" },
                 "role": { "type": "string", "description": "Target role (for market scan)" },
                 "industry": { "type": "string", "description": "Target industry (for market scan)" },
-                "goal_id": { "type": "integer", "description": "Goal ID (for progress report)" }
+                "goal_id": { "type": "integer", "description": "Goal ID (for progress report)" },
+                "text": { "type": "string", "description": "Pasted job posting text (for parse_posting)" },
+                "company": { "type": "string", "description": "Company name (overrides parsing / for applications)" },
+                "posting_id": { "type": "integer", "description": "Posting ID (for gap_analysis against a posting, or track_application)" },
+                "resume_path": { "type": "string", "description": "Workspace-relative resume file (for gap_analysis; defaults to resume.md/cv.md)" },
+                "application_id": { "type": "integer", "description": "Application ID (for update_application)" },
+                "status": {
+                    "type": "string",
+                    "enum": ["saved", "applied", "interviewing", "offer", "rejected"],
+                    "description": "Application status (saved → applied → interviewing → offer/rejected)"
+                },
+                "follow_up_days": { "type": "integer", "description": "Schedule a follow-up reminder this many days out (applications)" },
+                "follow_up_date": { "type": "string", "description": "Follow-up date YYYY-MM-DD (applications)" },
+                "needs_follow_up": { "type": "boolean", "description": "Only list applications whose follow-up is due" }
             },
             "required": ["action"]
         })
@@ -894,13 +1893,20 @@ impl Tool for CareerIntelTool {
             "set_goal" => self.set_goal(&args),
             "log_achievement" => self.log_achievement(&args),
             "add_portfolio" => self.add_portfolio(&args),
-            "gap_analysis" => self.gap_analysis(),
+            "gap_analysis" => match args.get("posting_id").and_then(|v| v.as_u64()) {
+                Some(pid) => self.posting_gap_analysis(&args, pid as usize),
+                None => self.gap_analysis(),
+            },
             "market_scan" => self.market_scan(&args),
             "network_note" => self.network_note(&args),
             "progress_report" => self.progress_report(&args),
             "strategy_review" => self.strategy_review(),
+            "parse_posting" => self.parse_posting(&args).await,
+            "track_application" => self.track_application(&args),
+            "update_application" => self.update_application(&args),
+            "list_applications" => self.list_applications(&args),
             _ => Ok(ToolOutput::text(format!(
-                "Unknown action: '{}'. Use: set_goal, log_achievement, add_portfolio, gap_analysis, market_scan, network_note, progress_report, strategy_review.",
+                "Unknown action: '{}'. Use: set_goal, log_achievement, add_portfolio, gap_analysis, market_scan, network_note, progress_report, strategy_review, parse_posting, track_application, update_application, list_applications.",
                 action
             ))),
         }
@@ -942,7 +1948,7 @@ mod tests {
         assert_eq!(schema["type"], "object");
         assert!(schema["properties"]["action"].is_object());
         let actions = schema["properties"]["action"]["enum"].as_array().unwrap();
-        assert_eq!(actions.len(), 8);
+        assert_eq!(actions.len(), 12);
         let required = schema["required"].as_array().unwrap();
         assert_eq!(required.len(), 1);
         assert_eq!(required[0], "action");
@@ -1237,4 +2243,188 @@ mod tests {
         assert!(result.content.contains("Unknown action"));
         assert!(result.content.contains("bogus"));
     }
+
+    const POSTING: &str = "Senior Backend Engineer at Acme Corp\n\
+        Location: Remote (EU)\n\
+        \n\
+        Requirements:\n\
+        - 5+ years of Rust or Go\n\
+        - Experience with Kubernetes and PostgreSQL\n\
+        \n\
+        Nice to have:\n\
+        - Kafka\n\
+        \n\
+        Salary: $150,000 - $180,000\n";
+
+    #[test]
+    fn test_parse_posting_text_extracts_fields() {
+        let posting = parse_posting_text(POSTING, &[]);
+        assert_eq!(posting.title, "Senior Backend Engineer");
+        assert_eq!(posting.company, "Acme Corp");
+        assert_eq!(posting.location.as_deref(), Some("Remote (EU)"));
+        assert!(posting.required_skills.contains(&"rust".to_string()));
+        assert!(posting.required_skills.contains(&"kubernetes".to_string()));
+        assert!(posting.required_skills.contains(&"postgresql".to_string()));
+        assert_eq!(posting.nice_to_have, vec!["kafka".to_string()]);
+        assert_eq!(
+            posting.compensation.as_deref(),
+            Some("Salary: $150,000 - $180,000")
+        );
+        assert!(!contains_term("javascript developer", "java"));
+    }
+
+    #[tokio::test]
+    async fn test_posting_gap_analysis_with_resume() {
+        let (dir, tool) = make_tool();
+        std::fs::create_dir_all(dir.path().join(".rustant/skills")).unwrap();
+        std::fs::write(
+            dir.path().join(".rustant/skills/tracker.json"),
+            r#"{"skills":[{"name":"Kubernetes","proficiency_level":60}]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("resume.md"),
+            "# Experience\n- Built payment services in Rust\n- Tuned PostgreSQL queries, cutting p99 by 40%\n",
+        )
+        .unwrap();
+
+        let parsed = tool
+            .execute(json!({ "action": "parse_posting", "text": POSTING }))
+            .await
+            .unwrap();
+        assert!(parsed.content.contains("Posting #1 saved"));
+
+        let result = tool
+            .execute(json!({ "action": "gap_analysis", "posting_id": 1 }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("Resume: resume.md"));
+        let meta = &result.metadata["gap_analysis"];
+        let matched: Vec<&str> = meta["matched"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert!(matched.contains(&"rust"));
+        assert!(matched.contains(&"kubernetes"));
+        assert_eq!(meta["missing_nice_to_have"], json!(["kafka"]));
+        let suggestions = meta["suggestions"].to_string();
+        assert!(suggestions.contains("kubernetes experience"));
+        assert!(suggestions.contains("Quantify"));
+
+        let bad = tool
+            .execute(
+                json!({ "action": "gap_analysis", "posting_id": 1, "resume_path": "../cv.md" }),
+            )
+            .await
+            .unwrap();
+        assert!(bad.content.contains("relative to the workspace"));
+    }
+
+    #[tokio::test]
+    async fn test_application_state_machine() {
+        let (_dir, tool) = make_tool();
+        let tracked = tool
+            .execute(json!({ "action": "track_application", "title": "SRE", "company": "Globex" }))
+            .await
+            .unwrap();
+        assert!(tracked.content.contains("[Saved]"));
+
+        let invalid = tool
+            .execute(
+                json!({ "action": "update_application", "application_id": 1, "status": "offer" }),
+            )
+            .await
+            .unwrap();
+        assert!(invalid.content.contains("Cannot move"));
+
+        let applied = tool
+            .execute(
+                json!({ "action": "update_application", "application_id": 1, "status": "applied" }),
+            )
+            .await
+            .unwrap();
+        assert!(applied.content.contains("Saved → Applied"));
+        assert!(applied.content.contains("follow-up on"));
+        tool.execute(json!({
+            "action": "update_application",
+            "application_id": 1,
+            "status": "interviewing",
+            "notes": "Phone screen booked"
+        }))
+        .await
+        .unwrap();
+
+        let listed = tool
+            .execute(json!({ "action": "list_applications", "status": "interviewing" }))
+            .await
+            .unwrap();
+        assert!(listed.content.contains("#1 [Interviewing] SRE at Globex"));
+        assert!(listed.content.contains("Phone screen booked"));
+        let none = tool
+            .execute(json!({ "action": "list_applications", "status": "offer" }))
+            .await
+            .unwrap();
+        assert!(none.content.contains("No matching applications"));
+
+        tool.execute(
+            json!({ "action": "update_application", "application_id": 1, "status": "rejected" }),
+        )
+        .await
+        .unwrap();
+        let state = tool.load_state();
+        assert_eq!(state.applications[0].history.len(), 4);
+        assert!(state.applications[0].follow_up_date.is_none());
+        assert!(state.applications[0].reminder_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_follow_up_reminders_and_briefing_line() {
+        let (dir, tool) = make_tool();
+        let workspace = dir.path().canonicalize().unwrap();
+        assert!(follow_up_briefing_line(&workspace).is_none());
+
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        for (title, company) in [("SRE", "Globex"), ("Data Engineer", "Initech")] {
+            tool.execute(json!({
+                "action": "track_application",
+                "title": title,
+                "company": company,
+                "status": "applied",
+                "follow_up_date": today
+            }))
+            .await
+            .unwrap();
+        }
+        tool.execute(json!({
+            "action": "track_application",
+            "title": "Later",
+            "company": "Umbrella",
+            "status": "applied",
+            "follow_up_days": 14
+        }))
+        .await
+        .unwrap();
+
+        let line = follow_up_briefing_line(&workspace).unwrap();
+        assert!(line.starts_with("2 applications need follow-up"));
+        assert!(line.contains("SRE at Globex"));
+
+        let mut bridge = SchedulerBridge::new(workspace.join(".rustant/reminders"));
+        assert_eq!(bridge.load_index().unwrap(), 3);
+        assert_eq!(bridge.active_reminders().len(), 3);
+        assert!(
+            bridge
+                .active_reminders()
+                .iter()
+                .all(|r| r.source_channel == "career")
+        );
+
+        let due = tool
+            .execute(json!({ "action": "list_applications", "needs_follow_up": true }))
+            .await
+            .unwrap();
+        assert!(due.content.contains("Applications (2)"));
+    }
 }
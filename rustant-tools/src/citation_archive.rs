@@ -0,0 +1,862 @@
+//! Citation archive — content snapshots of cited web sources, so research
+//! reports stay checkable after the live pages change or disappear.
+//!
+//! `archive` fetches a URL through the `web_fetch` politeness layer
+//! (robots.txt, per-host spacing, cache revalidation) and stores the
+//! extracted markdown under `.rustant/research/archive/`, keyed by URL hash.
+//! The index (`archive/index.json`) keeps the SHA-256 of the raw response and
+//! of the extracted text, the fetch time and the HTTP metadata. `report`
+//! records which snapshots a report cites and renders its references with
+//! both the live URL and the snapshot. `verify` re-fetches cited URLs and
+//! reports those that changed beyond `[tools.citation_archive]
+//! change_threshold` or vanished. Past `max_storage_mb`, the least recently
+//! used snapshots are evicted, never those cited by a saved report.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rustant_core::config::{CitationArchiveConfig, WebFetchConfig};
+use rustant_core::error::ToolError;
+use rustant_core::types::{RiskLevel, ToolOutput};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use similar::TextDiff;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::reader_capture::{normalize_url, url_key};
+use crate::registry::Tool;
+use crate::web::{PoliteFetch, polite_fetch};
+use crate::web_extract::extract_article;
+use crate::web_politeness::CachedResponse;
+
+/// Directory, relative to the workspace, holding snapshots and the index.
+pub const ARCHIVE_DIR: &str = ".rustant/research/archive";
+
+/// Characters of a snapshot shown by `show`.
+const PREVIEW_CHARS: usize = 1500;
+
+// ---------------------------------------------------------------------------
+// Data models
+// ---------------------------------------------------------------------------
+
+/// A cited web source with the snapshot that backs it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CitationSource {
+    pub url: String,
+    pub title: Option<String>,
+    /// Snapshot file, relative to the workspace.
+    pub snapshot: String,
+    /// SHA-256 of the archived text.
+    pub content_hash: String,
+    pub archived_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Snapshot {
+    /// Normalised URL (no fragment or tracking parameters).
+    url: String,
+    /// SHA-256 of `url`.
+    key: String,
+    title: Option<String>,
+    path: String,
+    /// URL after redirects.
+    final_url: String,
+    content_type: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// SHA-256 of the raw response body.
+    raw_hash: String,
+    /// SHA-256 of the archived text.
+    content_hash: String,
+    bytes: u64,
+    fetched_at: DateTime<Utc>,
+    last_used: DateTime<Utc>,
+}
+
+impl Snapshot {
+    fn citation(&self) -> CitationSource {
+        CitationSource {
+            url: self.url.clone(),
+            title: self.title.clone(),
+            snapshot: self.path.clone(),
+            content_hash: self.content_hash.clone(),
+            archived_at: self.fetched_at,
+        }
+    }
+}
+
+/// A saved report and the snapshots it cites, which are never evicted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedReport {
+    name: String,
+    keys: Vec<String>,
+    saved_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ArchiveIndex {
+    snapshots: Vec<Snapshot>,
+    reports: Vec<SavedReport>,
+}
+
+impl ArchiveIndex {
+    fn snapshot(&self, url: &str) -> Option<&Snapshot> {
+        let url = normalize_url(url);
+        self.snapshots.iter().find(|s| s.url == url)
+    }
+
+    fn cited_keys(&self) -> HashSet<&str> {
+        self.reports
+            .iter()
+            .flat_map(|r| r.keys.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+/// How a cited page compares with its snapshot.
+#[derive(Debug, Clone, PartialEq)]
+enum Verdict {
+    Unchanged,
+    /// Share of the text that differs, above the threshold.
+    Changed(f64),
+    /// 404 or 410.
+    Vanished(String),
+    /// Could not be checked: network error, other HTTP status, or robots.txt.
+    Unreachable(String),
+}
+
+// ---------------------------------------------------------------------------
+// Tool struct
+// ---------------------------------------------------------------------------
+
+pub struct CitationArchiveTool {
+    workspace: PathBuf,
+}
+
+impl CitationArchiveTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+
+    fn dir(&self) -> PathBuf {
+        self.workspace.join(ARCHIVE_DIR)
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir().join("index.json")
+    }
+
+    fn load_index(&self) -> ArchiveIndex {
+        std::fs::read_to_string(self.index_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_index(&self, index: &ArchiveIndex) -> Result<(), ToolError> {
+        let json = serde_json::to_string_pretty(index).map_err(|e| failed("serialize index", e))?;
+        write_atomic(&self.index_path(), &json)
+    }
+
+    fn configs(&self) -> (CitationArchiveConfig, WebFetchConfig) {
+        rustant_core::config::load_config(Some(&self.workspace), None)
+            .map(|c| (c.tools.citation_archive, c.tools.web_fetch))
+            .unwrap_or_default()
+    }
+
+    /// Fetch `url` politely. `Err` carries why nothing usable came back.
+    async fn fetch(
+        &self,
+        url: &str,
+        web: &WebFetchConfig,
+    ) -> Result<Result<CachedResponse, Verdict>, ToolError> {
+        let parsed = match reqwest::Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => parsed,
+            _ => {
+                return Ok(Err(Verdict::Unreachable("not an http(s) URL".to_string())));
+            }
+        };
+        match polite_fetch("citation_archive", url, &parsed, web, false).await {
+            Ok(PoliteFetch::Fetched { response, .. }) => Ok(Ok(response)),
+            Ok(PoliteFetch::Refused(reason)) => Ok(Err(Verdict::Unreachable(reason))),
+            Ok(PoliteFetch::Status { status, .. }) if matches!(status.as_u16(), 404 | 410) => {
+                Ok(Err(Verdict::Vanished(format!("HTTP {}", status))))
+            }
+            Ok(PoliteFetch::Status { status, .. }) => {
+                Ok(Err(Verdict::Unreachable(format!("HTTP {}", status))))
+            }
+            Err(ToolError::ExecutionFailed { message, .. }) => {
+                Ok(Err(Verdict::Unreachable(message)))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Write the snapshot of `url` from a fetched response and record it in
+    /// `index`, replacing an earlier snapshot of the same URL.
+    fn store(
+        &self,
+        index: &mut ArchiveIndex,
+        url: &str,
+        response: &CachedResponse,
+    ) -> Result<Snapshot, ToolError> {
+        let url = normalize_url(url);
+        let key = url_key(&url);
+        let (title, text) = snapshot_text(response);
+        let path = format!("{}/{}.md", ARCHIVE_DIR, &key[..16]);
+        let now = Utc::now();
+        let mut snapshot = Snapshot {
+            url,
+            key,
+            title,
+            path,
+            final_url: response.final_url.clone(),
+            content_type: response.content_type.clone(),
+            etag: response.etag.clone(),
+            last_modified: response.last_modified.clone(),
+            raw_hash: sha256(&response.body),
+            content_hash: sha256(&text),
+            bytes: 0,
+            fetched_at: now,
+            last_used: now,
+        };
+        let file = render_snapshot(&snapshot, &text);
+        snapshot.bytes = file.len() as u64;
+        write_atomic(&self.workspace.join(&snapshot.path), &file)?;
+
+        index.snapshots.retain(|s| s.key != snapshot.key);
+        index.snapshots.push(snapshot.clone());
+        Ok(snapshot)
+    }
+
+    /// The archived text of a snapshot, without its metadata header.
+    fn read_text(&self, snapshot: &Snapshot) -> Option<String> {
+        let file = std::fs::read_to_string(self.workspace.join(&snapshot.path)).ok()?;
+        Some(strip_header(&file).to_string())
+    }
+
+    /// Evict least recently used snapshots until the archive fits in
+    /// `max_bytes`, skipping those cited by a saved report and `keep`.
+    /// Returns the evicted URLs.
+    fn evict(&self, index: &mut ArchiveIndex, max_bytes: u64, keep: Option<&str>) -> Vec<String> {
+        let mut total: u64 = index.snapshots.iter().map(|s| s.bytes).sum();
+        if total <= max_bytes {
+            return Vec::new();
+        }
+        let cited: HashSet<String> = index.cited_keys().into_iter().map(String::from).collect();
+        let mut candidates: Vec<(DateTime<Utc>, String)> = index
+            .snapshots
+            .iter()
+            .filter(|s| !cited.contains(&s.key) && Some(s.key.as_str()) != keep)
+            .map(|s| (s.last_used, s.key.clone()))
+            .collect();
+        candidates.sort();
+
+        let mut evicted = Vec::new();
+        for (_, key) in candidates {
+            if total <= max_bytes {
+                break;
+            }
+            if let Some(i) = index.snapshots.iter().position(|s| s.key == key) {
+                let snapshot = index.snapshots.remove(i);
+                let _ = std::fs::remove_file(self.workspace.join(&snapshot.path));
+                total -= snapshot.bytes;
+                evicted.push(snapshot.url);
+            }
+        }
+        evicted
+    }
+
+    /// Archive `url` unless it already is (or `refresh` is set). Returns the
+    /// snapshot, or why the page could not be archived.
+    async fn archive_url(
+        &self,
+        index: &mut ArchiveIndex,
+        url: &str,
+        refresh: bool,
+        web: &WebFetchConfig,
+    ) -> Result<Result<(Snapshot, bool), String>, ToolError> {
+        if !refresh && let Some(existing) = index.snapshot(url).map(|s| s.key.clone()) {
+            let snapshot = index
+                .snapshots
+                .iter_mut()
+                .find(|s| s.key == existing)
+                .expect("snapshot found above");
+            snapshot.last_used = Utc::now();
+            return Ok(Ok((snapshot.clone(), false)));
+        }
+        match self.fetch(url, web).await? {
+            Ok(response) => Ok(Ok((self.store(index, url, &response)?, true))),
+            Err(Verdict::Vanished(reason) | Verdict::Unreachable(reason)) => Ok(Err(reason)),
+            Err(_) => unreachable!("fetch fails only as vanished or unreachable"),
+        }
+    }
+
+    // --- action helpers ---
+
+    async fn action_archive(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let url = str_arg(args, "url");
+        if url.is_empty() {
+            return Ok(ToolOutput::text("Please provide the 'url' to archive."));
+        }
+        let refresh = args
+            .get("refresh")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let (config, web) = self.configs();
+        let mut index = self.load_index();
+        let (snapshot, fetched) = match self.archive_url(&mut index, url, refresh, &web).await? {
+            Ok(result) => result,
+            Err(reason) => {
+                return Ok(ToolOutput::text(format!(
+                    "Not archived: {} ({})",
+                    url, reason
+                )));
+            }
+        };
+        let evicted = self.evict(
+            &mut index,
+            config.max_storage_mb * 1024 * 1024,
+            Some(&snapshot.key),
+        );
+        self.save_index(&index)?;
+
+        let mut out = if fetched {
+            format!("Archived {}\n", snapshot.url)
+        } else {
+            format!(
+                "Already archived {} (pass refresh: true to replace the snapshot)\n",
+                snapshot.url
+            )
+        };
+        out.push_str(&format_snapshot(&snapshot));
+        if !evicted.is_empty() {
+            out.push_str(&format!(
+                "Evicted {} least recently used snapshot(s) to stay under {} MB.\n",
+                evicted.len(),
+                config.max_storage_mb
+            ));
+        }
+        let citation = serde_json::to_string(&snapshot.citation())
+            .map_err(|e| failed("serialize citation", e))?;
+        out.push_str(&format!("Citation: {}", citation));
+        Ok(ToolOutput::text(out))
+    }
+
+    fn action_show(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let url = str_arg(args, "url");
+        let mut index = self.load_index();
+        let Some(key) = index.snapshot(url).map(|s| s.key.clone()) else {
+            return Ok(ToolOutput::text(format!("No snapshot of '{}'.", url)));
+        };
+        let snapshot = index
+            .snapshots
+            .iter_mut()
+            .find(|s| s.key == key)
+            .expect("snapshot found above");
+        snapshot.last_used = Utc::now();
+        let snapshot = snapshot.clone();
+        self.save_index(&index)?;
+
+        let mut out = format!("Snapshot of {}\n", snapshot.url);
+        out.push_str(&format_snapshot(&snapshot));
+        let reports: Vec<&str> = index
+            .reports
+            .iter()
+            .filter(|r| r.keys.contains(&key))
+            .map(|r| r.name.as_str())
+            .collect();
+        if !reports.is_empty() {
+            out.push_str(&format!("  Cited by: {}\n", reports.join(", ")));
+        }
+        match self.read_text(&snapshot) {
+            Some(text) => {
+                let preview: String = text.chars().take(PREVIEW_CHARS).collect();
+                out.push_str(&format!("\n{}", preview));
+                if text.chars().count() > PREVIEW_CHARS {
+                    out.push_str("\n[...]");
+                }
+            }
+            None => out.push_str("\n(snapshot file is missing)"),
+        }
+        Ok(ToolOutput::text(out))
+    }
+
+    fn action_list(&self) -> Result<ToolOutput, ToolError> {
+        let index = self.load_index();
+        if index.snapshots.is_empty() {
+            return Ok(ToolOutput::text(
+                "No archived citations. Use action 'archive' with a cited URL.",
+            ));
+        }
+        let (config, _) = self.configs();
+        let cited = index.cited_keys();
+        let total: u64 = index.snapshots.iter().map(|s| s.bytes).sum();
+        let mut snapshots: Vec<&Snapshot> = index.snapshots.iter().collect();
+        snapshots.sort_by_key(|s| std::cmp::Reverse(s.fetched_at));
+
+        let mut out = format!(
+            "{} archived citation(s), {:.1} of {} MB; {} saved report(s).\n",
+            snapshots.len(),
+            total as f64 / (1024.0 * 1024.0),
+            config.max_storage_mb,
+            index.reports.len()
+        );
+        for s in snapshots {
+            out.push_str(&format!(
+                "  {} {} — {}{}\n",
+                s.fetched_at.format("%Y-%m-%d"),
+                s.url,
+                s.title.as_deref().unwrap_or("(untitled)"),
+                if cited.contains(s.key.as_str()) {
+                    " [cited]"
+                } else {
+                    ""
+                }
+            ));
+        }
+        Ok(ToolOutput::text(out))
+    }
+
+    async fn action_report(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let name = str_arg(args, "name");
+        let urls = string_list(args, "urls");
+        if name.is_empty() || urls.is_empty() {
+            return Ok(ToolOutput::text(
+                "Please provide the report 'name' and the cited 'urls', in citation order.",
+            ));
+        }
+        let (config, web) = self.configs();
+        let mut index = self.load_index();
+        let mut entries = Vec::new();
+        for url in &urls {
+            let entry = self.archive_url(&mut index, url, false, &web).await?;
+            entries.push((url.clone(), entry.map(|(snapshot, _)| snapshot)));
+        }
+
+        let keys: Vec<String> = entries
+            .iter()
+            .filter_map(|(_, e)| e.as_ref().ok().map(|s| s.key.clone()))
+            .collect();
+        index.reports.retain(|r| r.name != name);
+        index.reports.push(SavedReport {
+            name: name.to_string(),
+            keys,
+            saved_at: Utc::now(),
+        });
+        self.evict(&mut index, config.max_storage_mb * 1024 * 1024, None);
+        self.save_index(&index)?;
+
+        let mut out = String::from("## References\n\n");
+        for (i, (url, entry)) in entries.iter().enumerate() {
+            out.push_str(&match entry {
+                Ok(snapshot) => format_reference(i + 1, snapshot),
+                Err(reason) => format!("{}. <{}> — not archived: {}\n", i + 1, url, reason),
+            });
+        }
+        Ok(ToolOutput::text(out))
+    }
+
+    fn action_delete_report(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let name = str_arg(args, "name");
+        let mut index = self.load_index();
+        let before = index.reports.len();
+        index.reports.retain(|r| r.name != name);
+        if index.reports.len() == before {
+            return Ok(ToolOutput::text(format!("No saved report '{}'.", name)));
+        }
+        self.save_index(&index)?;
+        Ok(ToolOutput::text(format!(
+            "Removed report '{}'. Its snapshots can now be evicted when the archive is full.",
+            name
+        )))
+    }
+
+    async fn action_verify(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let index = self.load_index();
+        let name = str_arg(args, "name");
+        let urls = string_list(args, "urls");
+        let snapshots: Vec<&Snapshot> = if !name.is_empty() {
+            let Some(report) = index.reports.iter().find(|r| r.name == name) else {
+                return Ok(ToolOutput::text(format!("No saved report '{}'.", name)));
+            };
+            index
+                .snapshots
+                .iter()
+                .filter(|s| report.keys.contains(&s.key))
+                .collect()
+        } else if !urls.is_empty() {
+            urls.iter().filter_map(|u| index.snapshot(u)).collect()
+        } else {
+            index.snapshots.iter().collect()
+        };
+        if snapshots.is_empty() {
+            return Ok(ToolOutput::text("No archived citations to verify."));
+        }
+
+        let (config, web) = self.configs();
+        let mut lines = Vec::new();
+        let (mut changed, mut vanished, mut unreachable) = (0, 0, 0);
+        for snapshot in snapshots {
+            let verdict = match self.fetch(&snapshot.url, &web).await? {
+                Ok(response) => {
+                    let (_, live) = snapshot_text(&response);
+                    let archived = self.read_text(snapshot).unwrap_or_default();
+                    classify(&archived, &live, config.change_threshold)
+                }
+                Err(verdict) => verdict,
+            };
+            let status = match &verdict {
+                Verdict::Unchanged => "unchanged".to_string(),
+                Verdict::Changed(ratio) => {
+                    changed += 1;
+                    format!("CHANGED ({:.0}% differs)", ratio * 100.0)
+                }
+                Verdict::Vanished(reason) => {
+                    vanished += 1;
+                    format!("VANISHED ({})", reason)
+                }
+                Verdict::Unreachable(reason) => {
+                    unreachable += 1;
+                    format!("not checked ({})", reason)
+                }
+            };
+            lines.push(format!(
+                "  {} — {} (archived {})",
+                snapshot.url,
+                status,
+                snapshot.fetched_at.format("%Y-%m-%d")
+            ));
+        }
+
+        let mut out = format!(
+            "Verified {} citation(s): {} changed, {} vanished, {} not checked (threshold {:.0}%).\n",
+            lines.len(),
+            changed,
+            vanished,
+            unreachable,
+            config.change_threshold * 100.0
+        );
+        out.push_str(&lines.join("\n"));
+        Ok(ToolOutput::text(out))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn failed(what: &str, e: impl std::fmt::Display) -> ToolError {
+    ToolError::ExecutionFailed {
+        name: "citation_archive".to_string(),
+        message: format!("Failed to {}: {}", what, e),
+    }
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> &'a str {
+    args.get(key).and_then(|v| v.as_str()).unwrap_or("").trim()
+}
+
+fn string_list(args: &Value, key: &str) -> Vec<String> {
+    args.get(key)
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.trim().to_string()))
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn sha256(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Title and text to archive: the extracted main content for HTML, the
+/// body as is for other text formats.
+fn snapshot_text(response: &CachedResponse) -> (Option<String>, String) {
+    let is_html = response.content_type.contains("text/html")
+        || response.content_type.contains("application/xhtml")
+        || (response.content_type.is_empty() && response.body.trim_start().starts_with('<'));
+    if is_html {
+        let base = reqwest::Url::parse(&response.final_url).ok();
+        let article = extract_article(&response.body, base.as_ref());
+        (article.title, article.markdown.trim().to_string())
+    } else {
+        (None, response.body.trim().to_string())
+    }
+}
+
+/// Share of `live` that differs from `archived`, judged against `threshold`.
+fn classify(archived: &str, live: &str, threshold: f64) -> Verdict {
+    let differs = 1.0 - TextDiff::from_lines(archived, live).ratio() as f64;
+    if differs > threshold {
+        Verdict::Changed(differs)
+    } else {
+        Verdict::Unchanged
+    }
+}
+
+/// Snapshot file: a metadata header, then the archived text.
+fn render_snapshot(snapshot: &Snapshot, text: &str) -> String {
+    let mut out = String::from("---\n");
+    out.push_str(&format!("url: {}\n", snapshot.url));
+    if snapshot.final_url != snapshot.url {
+        out.push_str(&format!("final_url: {}\n", snapshot.final_url));
+    }
+    if let Some(title) = &snapshot.title {
+        out.push_str(&format!("title: {:?}\n", title));
+    }
+    out.push_str(&format!(
+        "fetched_at: {}\n",
+        snapshot.fetched_at.to_rfc3339()
+    ));
+    out.push_str(&format!("content_type: {}\n", snapshot.content_type));
+    out.push_str(&format!("raw_sha256: {}\n", snapshot.raw_hash));
+    out.push_str(&format!("content_sha256: {}\n", snapshot.content_hash));
+    out.push_str("---\n\n");
+    out.push_str(text);
+    out.push('\n');
+    out
+}
+
+fn strip_header(file: &str) -> &str {
+    file.strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n\n"))
+        .map(|(_, text)| text)
+        .unwrap_or(file)
+        .trim_end_matches('\n')
+}
+
+fn format_snapshot(s: &Snapshot) -> String {
+    let mut out = String::new();
+    if let Some(title) = &s.title {
+        out.push_str(&format!("  Title: {}\n", title));
+    }
+    out.push_str(&format!("  Snapshot: {}\n", s.path));
+    out.push_str(&format!(
+        "  Fetched: {}\n",
+        s.fetched_at.format("%Y-%m-%d %H:%M UTC")
+    ));
+    if s.final_url != s.url {
+        out.push_str(&format!("  Redirected to: {}\n", s.final_url));
+    }
+    if !s.content_type.is_empty() {
+        out.push_str(&format!("  Content-Type: {}\n", s.content_type));
+    }
+    if let Some(etag) = &s.etag {
+        out.push_str(&format!("  ETag: {}\n", etag));
+    }
+    if let Some(modified) = &s.last_modified {
+        out.push_str(&format!("  Last-Modified: {}\n", modified));
+    }
+    out.push_str(&format!("  Content SHA-256: {}\n", s.content_hash));
+    out.push_str(&format!("  Raw response SHA-256: {}\n", s.raw_hash));
+    out
+}
+
+/// One numbered reference with the live URL and the archived snapshot.
+fn format_reference(n: usize, s: &Snapshot) -> String {
+    let title = s.title.as_deref().unwrap_or(&s.url);
+    format!(
+        "{}. [{}]({}) — archived {} as `{}` (SHA-256 `{}`)\n",
+        n,
+        title,
+        s.url,
+        s.fetched_at.format("%Y-%m-%d"),
+        s.path,
+        &s.content_hash[..16]
+    )
+}
+
+fn write_atomic(path: &std::path::Path, contents: &str) -> Result<(), ToolError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| failed("create archive dir", e))?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents).map_err(|e| failed("write snapshot", e))?;
+    std::fs::rename(&tmp, path).map_err(|e| failed("write snapshot", e))
+}
+
+// ---------------------------------------------------------------------------
+// Tool impl
+// ---------------------------------------------------------------------------
+
+#[async_trait]
+impl Tool for CitationArchiveTool {
+    fn name(&self) -> &str {
+        "citation_archive"
+    }
+
+    fn description(&self) -> &str {
+        "Archive snapshots of cited web sources so research stays reproducible. Call archive for each web source an answer or report cites; it stores the extracted text with content and raw-response hashes and returns the citation with its snapshot reference. report records the sources a report cites (keeping their snapshots from eviction) and renders its references with live URL and archive reference. verify re-fetches cited URLs and reports which changed materially or vanished. Respects robots.txt. Actions: archive, show, list, report, delete_report, verify."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["archive", "show", "list", "report", "delete_report", "verify"],
+                    "description": "Action to perform"
+                },
+                "url": { "type": "string", "description": "Cited URL (for archive and show)" },
+                "refresh": {
+                    "type": "boolean",
+                    "description": "Replace an existing snapshot with a fresh fetch (for archive, default: false)"
+                },
+                "name": { "type": "string", "description": "Report name (for report, delete_report, and verify)" },
+                "urls": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Cited URLs in citation order (for report), or URLs to check (for verify; default: all)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Write
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(300)
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolOutput, ToolError> {
+        let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("");
+
+        match action {
+            "archive" => self.action_archive(&args).await,
+            "show" => self.action_show(&args),
+            "list" => self.action_list(),
+            "report" => self.action_report(&args).await,
+            "delete_report" => self.action_delete_report(&args),
+            "verify" => self.action_verify(&args).await,
+            _ => Ok(ToolOutput::text(format!(
+                "Unknown action: '{}'. Use: archive, show, list, report, delete_report, verify",
+                action
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn make_tool() -> (TempDir, CitationArchiveTool) {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        (dir, CitationArchiveTool::new(workspace))
+    }
+
+    fn page(body: &str) -> CachedResponse {
+        CachedResponse {
+            body: format!(
+                "<html><head><title>Async Rust</title></head><body><article><p>{}</p></article></body></html>",
+                body
+            ),
+            content_type: "text/html; charset=utf-8".into(),
+            final_url: "https://example.com/async".into(),
+            etag: Some("\"v1\"".into()),
+            last_modified: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_report_renders_archived_references() {
+        let (_dir, tool) = make_tool();
+        let mut index = tool.load_index();
+        let snapshot = tool
+            .store(
+                &mut index,
+                "https://example.com/async#intro",
+                &page("Futures are lazy and do nothing unless polled."),
+            )
+            .unwrap();
+        tool.save_index(&index).unwrap();
+        assert_eq!(snapshot.url, "https://example.com/async");
+        assert_eq!(snapshot.title.as_deref(), Some("Async Rust"));
+        assert_eq!(
+            tool.read_text(&snapshot).map(|t| sha256(&t)),
+            Some(snapshot.content_hash.clone())
+        );
+
+        // Already archived, so no fetch is needed.
+        let out = tool
+            .execute(json!({"action": "report", "name": "survey", "urls": ["https://example.com/async"]}))
+            .await
+            .unwrap()
+            .content;
+        assert!(
+            out.contains("1. [Async Rust](https://example.com/async)"),
+            "{}",
+            out
+        );
+        assert!(out.contains(&snapshot.path));
+        assert!(out.contains(&snapshot.content_hash[..16]));
+        assert_eq!(tool.load_index().reports[0].keys, vec![snapshot.key]);
+    }
+
+    #[test]
+    fn test_eviction_spares_cited_snapshots() {
+        let (_dir, tool) = make_tool();
+        let mut index = ArchiveIndex::default();
+        let mut keys = Vec::new();
+        for (i, url) in ["https://a.test/", "https://b.test/", "https://c.test/"]
+            .iter()
+            .enumerate()
+        {
+            let mut snapshot = tool
+                .store(&mut index, url, &page(&"word ".repeat(200)))
+                .unwrap();
+            snapshot.last_used = Utc::now() - chrono::Duration::days(10 - i as i64);
+            index.snapshots.retain(|s| s.key != snapshot.key);
+            keys.push(snapshot.key.clone());
+            index.snapshots.push(snapshot);
+        }
+        // The oldest snapshot is cited, so the next oldest goes first.
+        index.reports.push(SavedReport {
+            name: "survey".into(),
+            keys: vec![keys[0].clone()],
+            saved_at: Utc::now(),
+        });
+        let size = index.snapshots[0].bytes;
+        let evicted = tool.evict(&mut index, size * 2, None);
+        assert_eq!(evicted, vec!["https://b.test/".to_string()]);
+        assert!(index.snapshot("https://a.test/").is_some());
+        assert!(
+            !tool
+                .workspace
+                .join(ARCHIVE_DIR)
+                .join(format!("{}.md", &keys[1][..16]))
+                .exists()
+        );
+
+        let evicted = tool.evict(&mut index, 0, None);
+        assert_eq!(evicted, vec!["https://c.test/".to_string()]);
+        assert_eq!(index.snapshots.len(), 1);
+    }
+
+    #[test]
+    fn test_classify_change_threshold() {
+        let archived = "one\ntwo\nthree\nfour\nfive\n";
+        assert_eq!(classify(archived, archived, 0.2), Verdict::Unchanged);
+        assert_eq!(
+            classify(archived, "one\ntwo\nthree\nfour\nfive!\n", 0.25),
+            Verdict::Unchanged
+        );
+        assert!(matches!(
+            classify(archived, "entirely\ndifferent\n", 0.2),
+            Verdict::Changed(r) if r > 0.5
+        ));
+    }
+}
@@ -4,11 +4,12 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use rustant_core::error::ToolError;
+use rustant_core::indexer::WorkspaceIgnore;
 use rustant_core::types::{RiskLevel, ToolOutput};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crate::registry::Tool;
@@ -145,6 +146,15 @@ impl CodeIntelligenceTool {
     // Helpers
     // -----------------------------------------------------------------------
 
+    /// Walk `root`, skipping what `.gitignore`, `.rustantignore` and the
+    /// `[index] ignore` patterns exclude.
+    fn walk(&self, root: &Path) -> ignore::Walk {
+        let mut builder = ignore::WalkBuilder::new(root);
+        builder.hidden(false).git_ignore(true);
+        WorkspaceIgnore::load(&self.workspace).apply(&mut builder);
+        builder.build()
+    }
+
     /// Resolve a path argument relative to the workspace.
     fn resolve_path(&self, args: &Value) -> PathBuf {
         args.get("path")
@@ -355,10 +365,7 @@ impl CodeIntelligenceTool {
         let mut total_lines: usize = 0;
         let max_files: usize = 5000;
 
-        let walker = ignore::WalkBuilder::new(&root)
-            .hidden(false)
-            .git_ignore(true)
-            .build();
+        let walker = self.walk(&root);
 
         for entry in walker {
             let entry = match entry {
@@ -457,7 +464,7 @@ impl CodeIntelligenceTool {
                 }
             })
             .collect();
-        languages.sort_by(|a, b| b.files.cmp(&a.files));
+        languages.sort_by_key(|b| std::cmp::Reverse(b.files));
 
         // Build directory list, sorted by file count descending.
         let mut directories: Vec<DirectoryInfo> = dir_map
@@ -468,7 +475,7 @@ impl CodeIntelligenceTool {
                 file_count,
             })
             .collect();
-        directories.sort_by(|a, b| b.file_count.cmp(&a.file_count));
+        directories.sort_by_key(|b| std::cmp::Reverse(b.file_count));
 
         entry_points.sort();
         config_files.sort();
@@ -508,10 +515,7 @@ impl CodeIntelligenceTool {
         let max_files: usize = 1000;
         let mut file_count: usize = 0;
 
-        let walker = ignore::WalkBuilder::new(&root)
-            .hidden(false)
-            .git_ignore(true)
-            .build();
+        let walker = self.walk(&root);
 
         for entry in walker {
             let entry = match entry {
@@ -592,83 +596,79 @@ impl CodeIntelligenceTool {
                 // Pattern-specific detection.
                 if let Some(filter) = pattern_filter {
                     match filter {
-                        "singleton" => {
-                            if (trimmed.contains("static")
-                                && (trimmed.contains("instance") || trimmed.contains("INSTANCE")))
+                        "singleton"
+                            if ((trimmed.contains("static")
+                                && (trimmed.contains("instance")
+                                    || trimmed.contains("INSTANCE")))
                                 || trimmed.contains("get_instance")
-                                || trimmed.contains("getInstance")
-                            {
-                                matches.push(PatternMatch {
-                                    pattern_name: "Singleton".to_string(),
-                                    file_path: rel.clone(),
-                                    line_number: line_num + 1,
-                                    snippet: trimmed.to_string(),
-                                    confidence: 0.8,
-                                });
-                            }
+                                || trimmed.contains("getInstance")) =>
+                        {
+                            matches.push(PatternMatch {
+                                pattern_name: "Singleton".to_string(),
+                                file_path: rel.clone(),
+                                line_number: line_num + 1,
+                                snippet: trimmed.to_string(),
+                                confidence: 0.8,
+                            });
                         }
-                        "factory" => {
-                            if trimmed.contains("fn create_")
+                        "factory"
+                            if (trimmed.contains("fn create_")
                                 || trimmed.contains("fn new_")
                                 || trimmed.contains("def create_")
                                 || trimmed.contains("function create")
-                                || trimmed.contains("Factory")
-                            {
-                                matches.push(PatternMatch {
-                                    pattern_name: "Factory".to_string(),
-                                    file_path: rel.clone(),
-                                    line_number: line_num + 1,
-                                    snippet: trimmed.to_string(),
-                                    confidence: 0.7,
-                                });
-                            }
+                                || trimmed.contains("Factory")) =>
+                        {
+                            matches.push(PatternMatch {
+                                pattern_name: "Factory".to_string(),
+                                file_path: rel.clone(),
+                                line_number: line_num + 1,
+                                snippet: trimmed.to_string(),
+                                confidence: 0.7,
+                            });
                         }
-                        "builder" => {
-                            if trimmed.contains("fn builder(")
+                        "builder"
+                            if (trimmed.contains("fn builder(")
                                 || trimmed.contains(".builder()")
                                 || trimmed.contains(".build()")
-                                || trimmed.contains("Builder")
-                            {
-                                matches.push(PatternMatch {
-                                    pattern_name: "Builder".to_string(),
-                                    file_path: rel.clone(),
-                                    line_number: line_num + 1,
-                                    snippet: trimmed.to_string(),
-                                    confidence: 0.7,
-                                });
-                            }
+                                || trimmed.contains("Builder")) =>
+                        {
+                            matches.push(PatternMatch {
+                                pattern_name: "Builder".to_string(),
+                                file_path: rel.clone(),
+                                line_number: line_num + 1,
+                                snippet: trimmed.to_string(),
+                                confidence: 0.7,
+                            });
                         }
-                        "observer" => {
-                            if trimmed.contains("on_event")
+                        "observer"
+                            if (trimmed.contains("on_event")
                                 || trimmed.contains("addEventListener")
                                 || trimmed.contains("subscribe")
                                 || trimmed.contains("notify")
-                                || trimmed.contains("Observer")
-                            {
-                                matches.push(PatternMatch {
-                                    pattern_name: "Observer".to_string(),
-                                    file_path: rel.clone(),
-                                    line_number: line_num + 1,
-                                    snippet: trimmed.to_string(),
-                                    confidence: 0.6,
-                                });
-                            }
+                                || trimmed.contains("Observer")) =>
+                        {
+                            matches.push(PatternMatch {
+                                pattern_name: "Observer".to_string(),
+                                file_path: rel.clone(),
+                                line_number: line_num + 1,
+                                snippet: trimmed.to_string(),
+                                confidence: 0.6,
+                            });
                         }
-                        "repository" => {
-                            if trimmed.contains("find_by")
+                        "repository"
+                            if (trimmed.contains("find_by")
                                 || trimmed.contains("findBy")
                                 || trimmed.contains("get_all")
                                 || trimmed.contains("getAll")
-                                || trimmed.contains("Repository")
-                            {
-                                matches.push(PatternMatch {
-                                    pattern_name: "Repository".to_string(),
-                                    file_path: rel.clone(),
-                                    line_number: line_num + 1,
-                                    snippet: trimmed.to_string(),
-                                    confidence: 0.7,
-                                });
-                            }
+                                || trimmed.contains("Repository")) =>
+                        {
+                            matches.push(PatternMatch {
+                                pattern_name: "Repository".to_string(),
+                                file_path: rel.clone(),
+                                line_number: line_num + 1,
+                                snippet: trimmed.to_string(),
+                                confidence: 0.7,
+                            });
                         }
                         _ => {}
                     }
@@ -852,54 +852,45 @@ impl CodeIntelligenceTool {
         for line in content.lines() {
             let trimmed = line.trim();
             match language {
-                "Rust" => {
+                "Rust"
                     if (trimmed.starts_with("fn ")
                         || trimmed.starts_with("pub fn ")
                         || trimmed.starts_with("pub(crate) fn ")
                         || trimmed.starts_with("async fn ")
                         || trimmed.starts_with("pub async fn "))
-                        && trimmed.contains('(')
-                    {
-                        count += 1;
-                    }
+                        && trimmed.contains('(') =>
+                {
+                    count += 1;
                 }
-                "Python" => {
-                    if trimmed.starts_with("def ") && trimmed.contains('(') {
-                        count += 1;
-                    }
+                "Python" if trimmed.starts_with("def ") && trimmed.contains('(') => {
+                    count += 1;
                 }
-                "JavaScript" | "JavaScript (JSX)" | "TypeScript" | "TypeScript (TSX)" => {
+                "JavaScript" | "JavaScript (JSX)" | "TypeScript" | "TypeScript (TSX)"
                     if (trimmed.starts_with("function ")
                         || trimmed.starts_with("async function ")
                         || trimmed.starts_with("export function ")
                         || trimmed.starts_with("export async function "))
-                        && trimmed.contains('(')
-                    {
-                        count += 1;
-                    }
+                        && trimmed.contains('(') =>
+                {
+                    count += 1;
                 }
-                "Go" => {
-                    if trimmed.starts_with("func ") && trimmed.contains('(') {
-                        count += 1;
-                    }
+                "Go" if trimmed.starts_with("func ") && trimmed.contains('(') => {
+                    count += 1;
                 }
-                "Java" | "C#" => {
+                "Java" | "C#"
                     if (trimmed.contains("public ")
                         || trimmed.contains("private ")
                         || trimmed.contains("protected "))
                         && trimmed.contains('(')
                         && !trimmed.contains("class ")
-                        && !trimmed.contains("interface ")
-                    {
-                        count += 1;
-                    }
+                        && !trimmed.contains("interface ") =>
+                {
+                    count += 1;
                 }
-                "Ruby" => {
-                    if trimmed.starts_with("def ") {
-                        count += 1;
-                    }
+                "Ruby" if trimmed.starts_with("def ") => {
+                    count += 1;
                 }
-                "C" | "C++" => {
+                "C" | "C++"
                     if trimmed.contains('(')
                         && trimmed.contains(')')
                         && (trimmed.ends_with('{') || trimmed.ends_with(") {"))
@@ -908,10 +899,9 @@ impl CodeIntelligenceTool {
                         && !trimmed.starts_with("while ")
                         && !trimmed.starts_with("switch ")
                         && !trimmed.starts_with("//")
-                        && !trimmed.starts_with('#')
-                    {
-                        count += 1;
-                    }
+                        && !trimmed.starts_with('#') =>
+                {
+                    count += 1;
                 }
                 _ => {}
             }
@@ -946,10 +936,7 @@ impl CodeIntelligenceTool {
         let max_files: usize = 1000;
         let mut file_count: usize = 0;
 
-        let walker = ignore::WalkBuilder::new(&root)
-            .hidden(false)
-            .git_ignore(true)
-            .build();
+        let walker = self.walk(&root);
 
         for entry in walker {
             let entry = match entry {
@@ -1214,10 +1201,7 @@ impl CodeIntelligenceTool {
         let lang_filter = args.get("language").and_then(|v| v.as_str());
         let mut entries: Vec<ApiEntry> = Vec::new();
 
-        let walker = ignore::WalkBuilder::new(&root)
-            .hidden(false)
-            .git_ignore(true)
-            .build();
+        let walker = self.walk(&root);
 
         for entry in walker {
             let entry = match entry {
@@ -1447,7 +1431,7 @@ impl CodeIntelligenceTool {
         let mut deps: Vec<DependencyEntry> = Vec::new();
 
         // Parse Cargo.toml files.
-        Self::find_and_parse_files(&root, "Cargo.toml", |path, content| {
+        self.find_and_parse_files(&root, "Cargo.toml", |path, content| {
             let rel = path
                 .strip_prefix(&root)
                 .unwrap_or(path)
@@ -1457,7 +1441,7 @@ impl CodeIntelligenceTool {
         });
 
         // Parse package.json files.
-        Self::find_and_parse_files(&root, "package.json", |path, content| {
+        self.find_and_parse_files(&root, "package.json", |path, content| {
             let rel = path
                 .strip_prefix(&root)
                 .unwrap_or(path)
@@ -1467,7 +1451,7 @@ impl CodeIntelligenceTool {
         });
 
         // Parse requirements.txt files.
-        Self::find_and_parse_files(&root, "requirements.txt", |path, content| {
+        self.find_and_parse_files(&root, "requirements.txt", |path, content| {
             let rel = path
                 .strip_prefix(&root)
                 .unwrap_or(path)
@@ -1477,7 +1461,7 @@ impl CodeIntelligenceTool {
         });
 
         // Parse go.mod files.
-        Self::find_and_parse_files(&root, "go.mod", |path, content| {
+        self.find_and_parse_files(&root, "go.mod", |path, content| {
             let rel = path
                 .strip_prefix(&root)
                 .unwrap_or(path)
@@ -1487,7 +1471,7 @@ impl CodeIntelligenceTool {
         });
 
         // Parse Gemfile files.
-        Self::find_and_parse_files(&root, "Gemfile", |path, content| {
+        self.find_and_parse_files(&root, "Gemfile", |path, content| {
             let rel = path
                 .strip_prefix(&root)
                 .unwrap_or(path)
@@ -1509,14 +1493,11 @@ impl CodeIntelligenceTool {
     // -----------------------------------------------------------------------
 
     /// Walk directory to find files with a specific name and call the handler.
-    fn find_and_parse_files<F>(root: &PathBuf, filename: &str, mut handler: F)
+    fn find_and_parse_files<F>(&self, root: &Path, filename: &str, mut handler: F)
     where
         F: FnMut(&std::path::Path, String),
     {
-        let walker = ignore::WalkBuilder::new(root)
-            .hidden(false)
-            .git_ignore(true)
-            .build();
+        let walker = self.walk(root);
 
         for entry in walker {
             let entry = match entry {
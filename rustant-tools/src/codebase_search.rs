@@ -2,14 +2,26 @@
 //!
 //! Provides semantic search over the indexed project files, function signatures,
 //! and content summaries. Requires the workspace to have been indexed first.
+//!
+//! Optional filters turn a search structured: symbol kind and name restrict
+//! hits to definition sites from the indexer's symbol table, while path globs
+//! and language narrow which files are considered. Filters are applied to the
+//! index's file list and symbol table, so only matching files are ever read.
+//!
+//! Quoted phrases must match verbatim, `offset` pages through results in a
+//! stable order, and `explain` shows how each hybrid result was scored.
 
 use crate::registry::Tool;
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use globset::{Glob, GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
 use rustant_core::error::ToolError;
-use rustant_core::indexer::ProjectIndexer;
-use rustant_core::search::SearchConfig;
+use rustant_core::indexer::{IndexerConfig, ProjectIndexer, Symbol, SymbolKind, language_for_path};
+use rustant_core::search::{QueryFilters, SearchConfig, SearchQuery, parse_query};
 use rustant_core::types::{RiskLevel, ToolOutput};
-use std::path::PathBuf;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 /// Tool for searching the project codebase using hybrid search.
@@ -43,13 +55,17 @@ impl CodebaseSearchTool {
                 ..Default::default()
             };
 
-            let mut indexer =
-                ProjectIndexer::new(self.workspace.clone(), search_config).map_err(|e| {
-                    ToolError::ExecutionFailed {
-                        name: "codebase_search".into(),
-                        message: format!("Failed to initialize indexer: {}", e),
-                    }
-                })?;
+            let config =
+                rustant_core::config::load_config(Some(&self.workspace), None).unwrap_or_default();
+            let mut indexer = ProjectIndexer::with_config(
+                self.workspace.clone(),
+                search_config,
+                IndexerConfig::from(&config.index),
+            )
+            .map_err(|e| ToolError::ExecutionFailed {
+                name: "codebase_search".into(),
+                message: format!("Failed to initialize indexer: {}", e),
+            })?;
 
             indexer.index_workspace();
             *guard = Some(indexer);
@@ -68,7 +84,10 @@ impl Tool for CodebaseSearchTool {
     fn description(&self) -> &str {
         "Search the project codebase using natural language queries. \
          Finds relevant files, function signatures, and code content. \
-         The workspace is automatically indexed on first use."
+         Optional filters narrow the search: `kind` and `name` return symbol \
+         definitions only, `include`/`exclude` globs, `language` and `modified_since` \
+         restrict which files are searched. Quote a phrase to match it exactly; use \
+         `offset` to fetch the next page. The workspace is automatically indexed on first use."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -78,26 +97,97 @@ impl Tool for CodebaseSearchTool {
                 "query": {
                     "type": "string",
                     "description": "Natural language search query (e.g., 'authentication handler', \
-                        'database connection', 'error types')"
+                        'database connection', 'error types'). Wrap words in double quotes \
+                        to match them as an exact phrase (e.g., '\"connection pool\" timeout'). \
+                        Optional when filters are given; with filters, hits must contain every \
+                        word and phrase of the query or rank semantically close to it."
+                },
+                "kind": {
+                    "type": ["string", "array"],
+                    "items": { "type": "string" },
+                    "description": "Only return symbol definitions of these kinds: function, \
+                        struct, enum, trait, class, interface, impl, module, type \
+                        (e.g., 'function' or ['struct', 'enum'])."
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Only return symbol definitions whose name matches. `*` is a \
+                        wildcard (e.g., '*parse*', 'handle_*'); without one, the name must \
+                        contain the text. Case-insensitive."
+                },
+                "include": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only search files matching one of these globs, relative to \
+                        the workspace (e.g., ['rustant-core/**', 'src/**/*.rs'])."
+                },
+                "exclude": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Skip files matching any of these globs (e.g., \
+                        ['**/tests/**', '**/*_test.go'])."
+                },
+                "language": {
+                    "type": "string",
+                    "description": "Only search files in this language, by name or extension \
+                        (e.g., 'rust', 'python', 'ts')."
+                },
+                "modified_since": {
+                    "type": "string",
+                    "description": "Only search files modified on or after this date \
+                        (YYYY-MM-DD or RFC 3339)."
+                },
+                "definitions_only": {
+                    "type": "boolean",
+                    "description": "Return symbol definition sites only, never comments, \
+                        strings or other text matches (default: false; implied by kind and name)."
                 },
                 "max_results": {
                     "type": "integer",
                     "description": "Maximum number of results to return (default: 10)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Results to skip, to fetch the next page of the same query \
+                        (default: 0). Ordering is stable across pages."
+                },
+                "explain": {
+                    "type": "boolean",
+                    "description": "Show each result's lexical, semantic and final scores and \
+                        the terms that matched (default: false)."
                 }
             },
-            "required": ["query"]
+            "examples": [
+                { "query": "authentication handler" },
+                { "kind": "function", "name": "*parse*", "include": ["rustant-core/**"], "exclude": ["**/tests/**"] },
+                { "query": "retry", "language": "rust", "definitions_only": true },
+                { "query": "\"connection pool\"", "modified_since": "2026-01-01", "offset": 10 }
+            ]
         })
     }
 
     async fn execute(&self, args: serde_json::Value) -> Result<ToolOutput, ToolError> {
+        let filters = SearchFilters::from_args(&args, &self.workspace).map_err(|reason| {
+            ToolError::InvalidArguments {
+                name: "codebase_search".into(),
+                reason,
+            }
+        })?;
         let query = args["query"]
             .as_str()
-            .ok_or_else(|| ToolError::InvalidArguments {
+            .map(str::trim)
+            .filter(|q| !q.is_empty());
+        if query.is_none() && !filters.wants_symbols() {
+            return Err(ToolError::InvalidArguments {
                 name: "codebase_search".into(),
-                reason: "'query' parameter is required".into(),
-            })?;
+                reason: "'query' parameter is required unless 'kind', 'name' or \
+                         'definitions_only' is given"
+                    .into(),
+            });
+        }
 
         let max_results = args["max_results"].as_u64().unwrap_or(10) as usize;
+        let offset = args["offset"].as_u64().unwrap_or(0) as usize;
 
         // Ensure workspace is indexed (lazy initialization)
         self.ensure_indexed()?;
@@ -115,36 +205,79 @@ impl Tool for CodebaseSearchTool {
             message: "Indexer not initialized".into(),
         })?;
 
-        let results = indexer
-            .search(query)
+        if filters.wants_symbols() {
+            let hits = symbol_search(indexer, &filters, query, offset + max_results)?;
+            let hits = page(hits, offset);
+            return Ok(ToolOutput::text(format_hits(
+                &hits,
+                query,
+                &filters,
+                offset,
+                max_results,
+            )));
+        }
+        let query = query.unwrap_or_default();
+        if filters.narrows_files() {
+            let hits = filtered_text_search(
+                indexer,
+                &self.workspace,
+                &filters,
+                query,
+                offset + max_results,
+            )?;
+            let hits = page(hits, offset);
+            return Ok(ToolOutput::text(format_hits(
+                &hits,
+                Some(query),
+                &filters,
+                offset,
+                max_results,
+            )));
+        }
+
+        let search = SearchQuery {
+            text: query.to_string(),
+            filters: QueryFilters::default(),
+            offset,
+            limit: Some(max_results),
+            explain: args["explain"].as_bool().unwrap_or(false),
+        };
+        let found = indexer
+            .search_query(&search)
             .map_err(|e| ToolError::ExecutionFailed {
                 name: "codebase_search".into(),
                 message: format!("Search failed: {}", e),
             })?;
 
-        if results.is_empty() {
+        if found.results.is_empty() {
             return Ok(ToolOutput::text(format!(
                 "No results found for query: '{}'",
                 query
             )));
         }
 
-        let mut output = format!(
-            "Found {} results for '{}':\n\n",
-            results.len().min(max_results),
-            query
-        );
+        let mut output = if offset == 0 {
+            format!("Found {} results for '{}':\n\n", found.total, query)
+        } else {
+            format!(
+                "Results {}-{} of {} for '{}':\n\n",
+                offset + 1,
+                offset + found.results.len(),
+                found.total,
+                query
+            )
+        };
 
-        for (i, result) in results.iter().take(max_results).enumerate() {
+        for (i, result) in found.results.iter().enumerate() {
             output.push_str(&format!(
                 "{}. [score: {:.2}] {}\n",
-                i + 1,
+                offset + i + 1,
                 result.combined_score,
                 result.content.lines().next().unwrap_or(&result.content)
             ));
 
             // Show a bit more context for top results
-            if i < 3 {
+            if offset + i < 3 {
                 let extra_lines: Vec<&str> = result.content.lines().skip(1).take(3).collect();
                 if !extra_lines.is_empty() {
                     for line in extra_lines {
@@ -152,8 +285,14 @@ impl Tool for CodebaseSearchTool {
                     }
                 }
             }
+            if let Some(explanation) = &result.explanation {
+                output.push_str(&format!("   why: {}\n", explanation));
+            }
             output.push('\n');
         }
+        if let Some(next) = found.next_offset {
+            output.push_str(&format!("More results: call again with offset {}.\n", next));
+        }
 
         Ok(ToolOutput::text(output))
     }
@@ -168,6 +307,480 @@ impl Tool for CodebaseSearchTool {
     }
 }
 
+/// Symbol-name pattern: a case-insensitive glob when it contains `*` or `?`,
+/// otherwise a case-insensitive substring.
+struct NamePattern {
+    raw: String,
+    glob: Option<GlobMatcher>,
+}
+
+impl NamePattern {
+    fn new(raw: &str) -> Result<Self, String> {
+        let glob = if raw.contains(['*', '?']) {
+            let glob = GlobBuilder::new(raw)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| format!("invalid name pattern '{}': {}", raw, e))?;
+            Some(glob.compile_matcher())
+        } else {
+            None
+        };
+        Ok(Self {
+            raw: raw.to_string(),
+            glob,
+        })
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match &self.glob {
+            Some(glob) => glob.is_match(name),
+            None => name.to_lowercase().contains(&self.raw.to_lowercase()),
+        }
+    }
+}
+
+/// Structured filters parsed from the tool arguments.
+struct SearchFilters {
+    kinds: Vec<SymbolKind>,
+    name: Option<NamePattern>,
+    include: Vec<String>,
+    include_set: Option<GlobSet>,
+    exclude: Vec<String>,
+    exclude_set: Option<GlobSet>,
+    language: Option<String>,
+    modified_since: Option<DateTime<Utc>>,
+    definitions_only: bool,
+    /// Workspace root, for reading modification times.
+    root: PathBuf,
+}
+
+impl SearchFilters {
+    fn from_args(args: &Value, root: &Path) -> Result<Self, String> {
+        let kinds = match &args["kind"] {
+            Value::Null => Vec::new(),
+            Value::String(kind) => vec![parse_kind(kind)?],
+            Value::Array(kinds) => kinds
+                .iter()
+                .map(|k| parse_kind(k.as_str().unwrap_or_default()))
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => return Err("'kind' must be a string or an array of strings".into()),
+        };
+        let name = args["name"]
+            .as_str()
+            .filter(|n| !n.trim().is_empty())
+            .map(|n| NamePattern::new(n.trim()))
+            .transpose()?;
+        let include = string_list(args, "include");
+        let exclude = string_list(args, "exclude");
+        let language = args["language"]
+            .as_str()
+            .map(|l| l.trim().trim_start_matches('.').to_lowercase())
+            .filter(|l| !l.is_empty());
+        let modified_since = args["modified_since"]
+            .as_str()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(parse_since)
+            .transpose()?;
+        Ok(Self {
+            kinds,
+            name,
+            include_set: build_globset(&include)?,
+            include,
+            exclude_set: build_globset(&exclude)?,
+            exclude,
+            language,
+            modified_since,
+            definitions_only: args["definitions_only"].as_bool().unwrap_or(false),
+            root: root.to_path_buf(),
+        })
+    }
+
+    /// Whether hits are restricted to symbol definitions.
+    fn wants_symbols(&self) -> bool {
+        self.definitions_only || !self.kinds.is_empty() || self.name.is_some()
+    }
+
+    /// Whether any path, language or date filter applies.
+    fn narrows_files(&self) -> bool {
+        self.include_set.is_some()
+            || self.exclude_set.is_some()
+            || self.language.is_some()
+            || self.modified_since.is_some()
+    }
+
+    /// Labels of the file filters `path` satisfies, or `None` when it is
+    /// filtered out.
+    fn file_match(&self, path: &str) -> Option<Vec<String>> {
+        let mut matched = Vec::new();
+        if let Some(include) = &self.include_set {
+            let first = *include.matches(path).first()?;
+            matched.push(format!("include={}", self.include[first]));
+        }
+        if let Some(exclude) = &self.exclude_set {
+            if exclude.is_match(path) {
+                return None;
+            }
+            matched.push(format!("exclude!={}", self.exclude.join(",")));
+        }
+        if let Some(language) = &self.language {
+            let ext = path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase());
+            if language_for_path(path) != Some(language.as_str())
+                && ext.as_deref() != Some(language.as_str())
+            {
+                return None;
+            }
+            matched.push(format!("language={}", language));
+        }
+        if let Some(since) = self.modified_since {
+            let modified = std::fs::metadata(self.root.join(path))
+                .and_then(|m| m.modified())
+                .ok()
+                .map(DateTime::<Utc>::from)?;
+            if modified < since {
+                return None;
+            }
+            matched.push(format!("modified>={}", since.format("%Y-%m-%d")));
+        }
+        Some(matched)
+    }
+
+    /// Labels of the symbol filters `symbol` satisfies, or `None` when it is
+    /// filtered out.
+    fn symbol_match(&self, symbol: &Symbol) -> Option<Vec<String>> {
+        let mut matched = Vec::new();
+        if !self.kinds.is_empty() {
+            if !self.kinds.contains(&symbol.kind) {
+                return None;
+            }
+            matched.push(format!("kind={}", symbol.kind));
+        }
+        if let Some(name) = &self.name {
+            if !name.matches(&symbol.name) {
+                return None;
+            }
+            matched.push(format!("name={}", name.raw));
+        }
+        if self.kinds.is_empty() && self.name.is_none() {
+            matched.push("definition".to_string());
+        }
+        Some(matched)
+    }
+}
+
+fn parse_kind(kind: &str) -> Result<SymbolKind, String> {
+    SymbolKind::parse(kind).ok_or_else(|| {
+        let valid: Vec<&str> = SymbolKind::ALL.iter().map(|k| k.as_str()).collect();
+        format!(
+            "unknown kind '{}'; expected one of: {}",
+            kind,
+            valid.join(", ")
+        )
+    })
+}
+
+/// A `YYYY-MM-DD` date (midnight UTC) or an RFC 3339 timestamp.
+fn parse_since(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| {
+            format!(
+                "'modified_since' must be YYYY-MM-DD or RFC 3339, not '{}'",
+                s
+            )
+        })
+}
+
+fn string_list(args: &Value, key: &str) -> Vec<String> {
+    match &args[key] {
+        Value::String(s) => vec![s.clone()],
+        other => serde_json::from_value(other.clone()).unwrap_or_default(),
+    }
+}
+
+fn build_globset(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).map_err(|e| format!("invalid glob '{}': {}", pattern, e))?);
+    }
+    builder.build().map(Some).map_err(|e| e.to_string())
+}
+
+/// One search result with the filters it satisfied.
+struct Hit {
+    path: String,
+    line: Option<usize>,
+    text: String,
+    /// Signature of the enclosing symbol, for text hits inside a definition.
+    context: Option<String>,
+    matched: Vec<String>,
+}
+
+/// Whether `text` contains every word and quoted phrase of `query`.
+fn contains_terms(text: &str, query: &str) -> bool {
+    parse_query(query).matches(text)
+}
+
+/// Drop the hits before `offset`.
+fn page(mut hits: Vec<Hit>, offset: usize) -> Vec<Hit> {
+    hits.drain(..offset.min(hits.len()));
+    hits
+}
+
+/// Semantic scores from the hybrid index, keyed by `(path, line)` for
+/// signature entries and by path for file entries.
+#[derive(Default)]
+struct SemanticScores {
+    lines: HashMap<(String, usize), f32>,
+    files: HashMap<String, f32>,
+}
+
+impl SemanticScores {
+    fn collect(indexer: &ProjectIndexer, query: &str) -> Result<Self, ToolError> {
+        let results = indexer
+            .search(query)
+            .map_err(|e| ToolError::ExecutionFailed {
+                name: "codebase_search".into(),
+                message: format!("Search failed: {}", e),
+            })?;
+        let mut scores = Self::default();
+        for result in results {
+            if result.fact_id.starts_with("sig:") {
+                // Signature entries read "path:line signature".
+                let location = result.content.split_whitespace().next().unwrap_or("");
+                if let Some((path, line)) = location.rsplit_once(':')
+                    && let Ok(line) = line.parse::<usize>()
+                {
+                    scores
+                        .lines
+                        .insert((path.to_string(), line), result.combined_score);
+                }
+            } else if let Some(path) = result
+                .fact_id
+                .strip_prefix("file:")
+                .or_else(|| result.fact_id.strip_prefix("content:"))
+            {
+                let score = scores.files.entry(path.to_string()).or_insert(0.0);
+                *score = score.max(result.combined_score);
+            }
+        }
+        Ok(scores)
+    }
+}
+
+/// Definition sites from the symbol table that pass every filter, ranked by
+/// relevance to `query` when one is given.
+fn symbol_search(
+    indexer: &ProjectIndexer,
+    filters: &SearchFilters,
+    query: Option<&str>,
+    max_results: usize,
+) -> Result<Vec<Hit>, ToolError> {
+    let files: HashMap<&str, Vec<String>> = indexer
+        .files()
+        .iter()
+        .filter_map(|path| filters.file_match(path).map(|m| (path.as_str(), m)))
+        .collect();
+    let semantic = match query {
+        Some(q) => SemanticScores::collect(indexer, q)?,
+        None => SemanticScores::default(),
+    };
+
+    let mut ranked: Vec<(f32, Hit)> = Vec::new();
+    for symbol in indexer.symbols() {
+        let Some(file_matched) = files.get(symbol.path.as_str()) else {
+            continue;
+        };
+        let Some(symbol_matched) = filters.symbol_match(symbol) else {
+            continue;
+        };
+        let mut matched = file_matched.clone();
+        matched.extend(symbol_matched);
+
+        let mut score = 0.0;
+        if let Some(q) = query {
+            if contains_terms(&symbol.signature, q) {
+                score += 1.0;
+                matched.push("text".to_string());
+            }
+            let close = semantic
+                .lines
+                .get(&(symbol.path.clone(), symbol.line))
+                .copied()
+                .or_else(|| semantic.files.get(&symbol.path).map(|s| s * 0.5))
+                .unwrap_or(0.0);
+            if close > 0.0 {
+                score += close;
+                matched.push("semantic".to_string());
+            }
+            if score <= 0.0 {
+                continue;
+            }
+        }
+        ranked.push((
+            score,
+            Hit {
+                path: symbol.path.clone(),
+                line: Some(symbol.line),
+                text: format!("[{}] {}", symbol.kind, symbol.signature),
+                context: None,
+                matched,
+            },
+        ));
+    }
+    // Stable sort keeps file and line order among equal scores.
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Ok(ranked
+        .into_iter()
+        .take(max_results)
+        .map(|(_, hit)| hit)
+        .collect())
+}
+
+/// Text matches in the files that pass the path and language filters,
+/// topped up with semantic matches from the same files. Only files from the
+/// index that pass the filters are read, and reading stops once enough hits
+/// are found.
+fn filtered_text_search(
+    indexer: &ProjectIndexer,
+    workspace: &std::path::Path,
+    filters: &SearchFilters,
+    query: &str,
+    max_results: usize,
+) -> Result<Vec<Hit>, ToolError> {
+    let mut hits = Vec::new();
+    let mut matched_files: Vec<(&str, Vec<String>)> = Vec::new();
+    'files: for path in indexer.files() {
+        let Some(matched) = filters.file_match(path) else {
+            continue;
+        };
+        matched_files.push((path.as_str(), matched.clone()));
+        let Ok(content) = std::fs::read_to_string(workspace.join(path)) else {
+            continue;
+        };
+        for (i, line) in content.lines().enumerate() {
+            if !contains_terms(line, query) {
+                continue;
+            }
+            let mut matched = matched.clone();
+            matched.push("text".to_string());
+            hits.push(Hit {
+                path: path.clone(),
+                line: Some(i + 1),
+                text: line.trim().to_string(),
+                context: indexer
+                    .enclosing_symbol(path, i + 1)
+                    .map(|s| s.signature.clone()),
+                matched,
+            });
+            if hits.len() >= max_results {
+                break 'files;
+            }
+        }
+    }
+
+    if hits.len() < max_results {
+        let semantic = SemanticScores::collect(indexer, query)?;
+        let seen: HashSet<(String, Option<usize>)> =
+            hits.iter().map(|h| (h.path.clone(), h.line)).collect();
+        let mut extra: Vec<(f32, Hit)> = Vec::new();
+        for ((path, line), score) in &semantic.lines {
+            let Some(matched) = filters.file_match(path) else {
+                continue;
+            };
+            if seen.contains(&(path.clone(), Some(*line))) {
+                continue;
+            }
+            let symbol = indexer.enclosing_symbol(path, *line);
+            let mut matched = matched;
+            matched.push("semantic".to_string());
+            extra.push((
+                *score,
+                Hit {
+                    path: path.clone(),
+                    line: Some(*line),
+                    text: symbol.map(|s| s.signature.clone()).unwrap_or_default(),
+                    context: None,
+                    matched,
+                },
+            ));
+        }
+        for (path, matched) in matched_files {
+            if let Some(score) = semantic.files.get(path)
+                && !hits.iter().any(|h| h.path == path)
+            {
+                let mut matched = matched;
+                matched.push("semantic".to_string());
+                extra.push((
+                    *score,
+                    Hit {
+                        path: path.to_string(),
+                        line: None,
+                        text: String::new(),
+                        context: None,
+                        matched,
+                    },
+                ));
+            }
+        }
+        extra.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let room = max_results - hits.len();
+        hits.extend(extra.into_iter().take(room).map(|(_, hit)| hit));
+    }
+    Ok(hits)
+}
+
+fn format_hits(
+    hits: &[Hit],
+    query: Option<&str>,
+    filters: &SearchFilters,
+    offset: usize,
+    max_results: usize,
+) -> String {
+    let subject = match query {
+        Some(q) => format!("'{}'", q),
+        None => "the given filters".to_string(),
+    };
+    if hits.is_empty() {
+        let what = if filters.wants_symbols() {
+            "definitions"
+        } else {
+            "results"
+        };
+        return format!("No {} found for {}", what, subject);
+    }
+    let mut output = format!("Found {} results for {}:\n\n", hits.len(), subject);
+    for (i, hit) in hits.iter().enumerate() {
+        let location = match hit.line {
+            Some(line) => format!("{}:{}", hit.path, line),
+            None => hit.path.clone(),
+        };
+        output.push_str(&format!("{}. {}", offset + i + 1, location));
+        if !hit.text.is_empty() {
+            output.push(' ');
+            output.push_str(&hit.text);
+        }
+        output.push('\n');
+        if let Some(context) = &hit.context {
+            output.push_str(&format!("   in: {}\n", context));
+        }
+        output.push_str(&format!("   matched: {}\n\n", hit.matched.join(", ")));
+    }
+    if hits.len() >= max_results {
+        output.push_str(&format!(
+            "More results may follow: call again with offset {}.\n",
+            offset + hits.len()
+        ));
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -234,6 +847,100 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_codebase_search_definitions_with_filters() {
+        let (_dir, path) = setup_workspace();
+        fs::write(
+            path.join("src/parser.rs"),
+            "pub fn parse_config(input: &str) -> bool {\n    // parse_config is called at startup\n    input.is_empty()\n}\n\npub struct ParseError;\n",
+        )
+        .unwrap();
+        fs::create_dir_all(path.join("tests")).unwrap();
+        fs::write(path.join("tests/parse_test.rs"), "fn parse_fixture() {}\n").unwrap();
+        let tool = CodebaseSearchTool::new(path);
+
+        let args = serde_json::json!({
+            "kind": "function",
+            "name": "*PARSE*",
+            "exclude": ["tests/**"]
+        });
+        let result = tool.execute(args).await.unwrap().content;
+        assert!(
+            result
+                .contains("1. src/parser.rs:1 [function] pub fn parse_config(input: &str) -> bool"),
+            "{}",
+            result
+        );
+        assert!(result.contains("matched: exclude!=tests/**, kind=function, name=*PARSE*"));
+        assert!(!result.contains("parse_fixture"));
+        assert!(!result.contains("ParseError"));
+        // The comment mentioning parse_config is not a definition site.
+        assert!(!result.contains("src/parser.rs:2"));
+    }
+
+    #[tokio::test]
+    async fn test_codebase_search_text_with_path_filter() {
+        let (_dir, path) = setup_workspace();
+        let tool = CodebaseSearchTool::new(path);
+
+        let args = serde_json::json!({"query": "starting", "include": ["src/**"]});
+        let result = tool.execute(args).await.unwrap().content;
+        assert!(
+            result.contains("1. src/main.rs:6 println!(\"starting\");"),
+            "{}",
+            result
+        );
+        assert!(result.contains("in: fn run_server()"));
+        assert!(result.contains("matched: include=src/**, text"));
+
+        let args = serde_json::json!({"query": "starting", "language": "python"});
+        let result = tool.execute(args).await.unwrap().content;
+        assert_eq!(result, "No results found for 'starting'");
+    }
+
+    #[tokio::test]
+    async fn test_codebase_search_phrase_and_offset() {
+        let (_dir, path) = setup_workspace();
+        let tool = CodebaseSearchTool::new(path);
+
+        // Only the definition line holds the exact phrase, not the call.
+        let args = serde_json::json!({"query": "\"fn run_server\"", "include": ["src/**"]});
+        let result = tool.execute(args).await.unwrap().content;
+        assert!(
+            result.contains("1. src/main.rs:5 fn run_server() {"),
+            "{}",
+            result
+        );
+        assert!(!result.contains("src/main.rs:2 "));
+
+        let args = serde_json::json!({"kind": "function", "max_results": 1});
+        let first = tool.execute(args).await.unwrap().content;
+        assert!(first.contains("call again with offset 1."), "{}", first);
+        let args = serde_json::json!({"kind": "function", "max_results": 1, "offset": 1});
+        let second = tool.execute(args).await.unwrap().content;
+        assert!(second.contains("\n2. "), "{}", second);
+        let location = |out: &str| {
+            out.lines()
+                .find(|l| l.contains(". src/"))
+                .map(str::to_string)
+        };
+        assert_ne!(location(&first), location(&second));
+
+        let args = serde_json::json!({"query": "main", "modified_since": "last week"});
+        let err = tool.execute(args).await.unwrap_err();
+        assert!(err.to_string().contains("modified_since"));
+    }
+
+    #[tokio::test]
+    async fn test_codebase_search_invalid_kind() {
+        let (_dir, path) = setup_workspace();
+        let tool = CodebaseSearchTool::new(path);
+
+        let args = serde_json::json!({"kind": ["function", "widget"]});
+        let err = tool.execute(args).await.unwrap_err();
+        assert!(err.to_string().contains("unknown kind 'widget'"));
+    }
+
     #[test]
     fn test_tool_properties() {
         let dir = TempDir::new().unwrap();
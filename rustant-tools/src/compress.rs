@@ -1,22 +1,643 @@
-//! Compression tool — create and extract zip archives.
+//! Compression tool — streaming zip and tar.gz archives.
+//!
+//! Archives are created and extracted entry-by-entry without loading whole
+//! files into memory. Creation supports include/exclude globs, optional AES-256
+//! zip encryption with the passphrase resolved from a `SecretRef`, and never
+//! follows symlinks unless asked. Extraction is validated up front against
+//! path traversal (zip-slip), link entries, entry-count caps, and
+//! decompression-bomb ratios before anything is written to disk.
 
 use async_trait::async_trait;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rustant_core::credentials::configured_store;
 use rustant_core::error::ToolError;
-use rustant_core::types::{RiskLevel, ToolOutput};
+use rustant_core::secret_ref::{SecretRef, SecretResolver};
+use rustant_core::types::{ProgressUpdate, RiskLevel, ToolOutput};
+use serde::Serialize;
 use serde_json::{Value, json};
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::time::Duration;
+use tokio::sync::mpsc;
 
 use crate::registry::Tool;
 
+/// Default maximum number of entries accepted when extracting.
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+/// Default maximum total uncompressed bytes accepted when extracting (16 GiB).
+const DEFAULT_MAX_TOTAL_BYTES: u64 = 16 * 1024 * 1024 * 1024;
+/// Default maximum uncompressed/compressed ratio accepted when extracting.
+const DEFAULT_MAX_RATIO: f64 = 100.0;
+/// Maximum number of manifest entries rendered in the text output.
+const MANIFEST_PREVIEW: usize = 50;
+
+/// Supported archive formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Resolve the format from an explicit name or the archive file extension.
+    fn detect(explicit: Option<&str>, archive: &str) -> Option<Self> {
+        match explicit {
+            Some("zip") => Some(Self::Zip),
+            Some("tar.gz") | Some("tgz") => Some(Self::TarGz),
+            Some(_) => None,
+            None => {
+                let lower = archive.to_ascii_lowercase();
+                if lower.ends_with(".zip") {
+                    Some(Self::Zip)
+                } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+                    Some(Self::TarGz)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// A policy violation detected while creating or extracting an archive.
+#[derive(Debug, Clone, PartialEq, Serialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ArchiveViolation {
+    #[error("entry '{entry}' would be written outside the output directory")]
+    PathTraversal { entry: String },
+    #[error("entry '{entry}' is a link; link entries are not extracted")]
+    LinkEntry { entry: String },
+    #[error("archive has more than {limit} entries")]
+    EntryCountExceeded { limit: usize },
+    #[error("archive would expand to more than {limit} bytes")]
+    TotalSizeExceeded { limit: u64 },
+    #[error("entry '{entry}' has compression ratio {ratio:.1} (limit {limit:.1})")]
+    CompressionRatioExceeded {
+        entry: String,
+        ratio: f64,
+        limit: f64,
+    },
+    #[error("entry '{entry}' expanded beyond its declared size")]
+    SizeMismatch { entry: String },
+    #[error("path '{path}' is outside the workspace")]
+    OutsideWorkspace { path: String },
+    #[error("encryption is only supported for zip archives")]
+    EncryptionUnsupported,
+    #[error("passphrase must be a SecretRef (keychain:<account> or env:<VAR>)")]
+    InlinePassphrase,
+}
+
+/// Extraction limits, overridable per call.
+#[derive(Debug, Clone, Copy)]
+struct ExtractLimits {
+    max_entries: usize,
+    max_total_bytes: u64,
+    max_ratio: f64,
+}
+
+/// One entry in an archive manifest.
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compressed_size: Option<u64>,
+}
+
+/// Summary of an archive's contents returned with every action.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveManifest {
+    pub archive: String,
+    pub format: ArchiveFormat,
+    pub encrypted: bool,
+    pub entries: Vec<ManifestEntry>,
+    pub total_uncompressed: u64,
+    pub total_compressed: u64,
+    /// Paths skipped during creation (symlinks, unreadable files).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+}
+
+impl ArchiveManifest {
+    fn new(archive: &str, format: ArchiveFormat, encrypted: bool) -> Self {
+        Self {
+            archive: archive.to_string(),
+            format,
+            encrypted,
+            entries: Vec::new(),
+            total_uncompressed: 0,
+            total_compressed: 0,
+            skipped: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, path: String, size: u64, compressed_size: Option<u64>) {
+        self.total_uncompressed += size;
+        self.entries.push(ManifestEntry {
+            path,
+            size,
+            compressed_size,
+        });
+    }
+
+    fn into_output(self, headline: String) -> ToolOutput {
+        let mut text = format!(
+            "{}\n{} entries, {} bytes uncompressed, {} bytes compressed{}\n",
+            headline,
+            self.entries.len(),
+            self.total_uncompressed,
+            self.total_compressed,
+            if self.encrypted { " (AES-256)" } else { "" }
+        );
+        for entry in self.entries.iter().take(MANIFEST_PREVIEW) {
+            text.push_str(&format!("  {} ({} bytes)\n", entry.path, entry.size));
+        }
+        if self.entries.len() > MANIFEST_PREVIEW {
+            text.push_str(&format!(
+                "  ... and {} more\n",
+                self.entries.len() - MANIFEST_PREVIEW
+            ));
+        }
+        if !self.skipped.is_empty() {
+            text.push_str(&format!("Skipped {} path(s): ", self.skipped.len()));
+            text.push_str(&self.skipped.join(", "));
+            text.push('\n');
+        }
+        let mut output = ToolOutput::text(text);
+        output.metadata.insert(
+            "manifest".into(),
+            serde_json::to_value(&self).unwrap_or(Value::Null),
+        );
+        output
+    }
+}
+
+/// Internal failure: either a policy violation or an I/O-level error.
+enum ArchiveFailure {
+    Violation(ArchiveViolation),
+    Io(String),
+}
+
+impl From<ArchiveViolation> for ArchiveFailure {
+    fn from(v: ArchiveViolation) -> Self {
+        Self::Violation(v)
+    }
+}
+
+impl From<std::io::Error> for ArchiveFailure {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for ArchiveFailure {
+    fn from(e: zip::result::ZipError) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+/// Sends progress updates for long-running archive operations.
+#[derive(Clone)]
+struct Progress {
+    tx: Option<mpsc::UnboundedSender<ProgressUpdate>>,
+    total: usize,
+}
+
+impl Progress {
+    fn entry(&self, stage: &str, index: usize, path: &Path, bytes: u64) {
+        if let Some(tx) = &self.tx {
+            let percent = if self.total > 0 {
+                Some((index + 1) as f32 / self.total as f32)
+            } else {
+                None
+            };
+            let _ = tx.send(ProgressUpdate::ToolProgress {
+                tool: "compress".into(),
+                stage: format!("{} {}/{}", stage, index + 1, self.total),
+                percent,
+            });
+            let _ = tx.send(ProgressUpdate::FileOperation {
+                path: path.to_path_buf(),
+                operation: stage.to_string(),
+                bytes_processed: Some(bytes),
+            });
+        }
+    }
+}
+
 pub struct CompressTool {
     workspace: PathBuf,
+    progress_tx: Option<mpsc::UnboundedSender<ProgressUpdate>>,
 }
 
 impl CompressTool {
     pub fn new(workspace: PathBuf) -> Self {
-        Self { workspace }
+        Self {
+            workspace,
+            progress_tx: None,
+        }
+    }
+
+    /// Create a compress tool that streams per-entry progress updates.
+    pub fn with_progress(workspace: PathBuf, tx: mpsc::UnboundedSender<ProgressUpdate>) -> Self {
+        Self {
+            workspace,
+            progress_tx: Some(tx),
+        }
+    }
+
+    /// Join a user-supplied relative path onto the workspace, rejecting escapes.
+    fn resolve(&self, rel: &str) -> Result<PathBuf, ArchiveViolation> {
+        let path = Path::new(rel);
+        if path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+            return Err(ArchiveViolation::OutsideWorkspace {
+                path: rel.to_string(),
+            });
+        }
+        Ok(self.workspace.join(path))
+    }
+}
+
+fn build_globset(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).map_err(|e| format!("invalid glob '{}': {}", pattern, e))?);
+    }
+    builder.build().map(Some).map_err(|e| e.to_string())
+}
+
+fn string_list(args: &Value, key: &str) -> Vec<String> {
+    args.get(key)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Resolve the optional `password_ref` argument. Inline plaintext is refused so
+/// passphrases never appear in transcripts.
+fn resolve_password(args: &Value) -> Result<Option<String>, String> {
+    let Some(raw) = args.get("password_ref").and_then(|v| v.as_str()) else {
+        return Ok(None);
+    };
+    let secret = SecretRef::from(raw);
+    if !secret.is_keychain() && !secret.is_env() {
+        return Err(ArchiveViolation::InlinePassphrase.to_string());
+    }
+    SecretResolver::resolve(&secret, configured_store().as_ref())
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+/// A file selected for archiving: absolute source path and in-archive name.
+struct SourceFile {
+    path: PathBuf,
+    name: String,
+    size: u64,
+}
+
+/// Walk the requested paths and select files matching the include/exclude globs.
+/// The archive being written is never selected, even when it sits inside a
+/// source directory.
+fn collect_sources(
+    workspace: &Path,
+    archive_path: &Path,
+    roots: &[PathBuf],
+    include: Option<&GlobSet>,
+    exclude: Option<&GlobSet>,
+    follow_symlinks: bool,
+    skipped: &mut Vec<String>,
+) -> Vec<SourceFile> {
+    let mut sources = Vec::new();
+    for root in roots {
+        for entry in walkdir::WalkDir::new(root)
+            .follow_links(follow_symlinks)
+            .sort_by_file_name()
+        {
+            let entry = match entry {
+                Ok(e) => e,
+                Err(e) => {
+                    skipped.push(
+                        e.path()
+                            .map(|p| p.display().to_string())
+                            .unwrap_or_default(),
+                    );
+                    continue;
+                }
+            };
+            let rel = entry
+                .path()
+                .strip_prefix(workspace)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .replace('\\', "/");
+            if entry.path_is_symlink() && !follow_symlinks {
+                skipped.push(rel);
+                continue;
+            }
+            if !entry.file_type().is_file() || entry.path() == archive_path {
+                continue;
+            }
+            if include.is_some_and(|g| !g.is_match(&rel))
+                || exclude.is_some_and(|g| g.is_match(&rel))
+            {
+                continue;
+            }
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            sources.push(SourceFile {
+                path: entry.path().to_path_buf(),
+                name: rel,
+                size,
+            });
+        }
+    }
+    sources
+}
+
+fn create_archive(
+    archive_path: &Path,
+    format: ArchiveFormat,
+    sources: Vec<SourceFile>,
+    password: Option<&str>,
+    mut manifest: ArchiveManifest,
+    progress: Progress,
+) -> Result<ArchiveManifest, ArchiveFailure> {
+    if let Some(parent) = archive_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = std::fs::File::create(archive_path)?;
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipWriter::new(file);
+            for (i, source) in sources.iter().enumerate() {
+                let mut options = zip::write::SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated)
+                    .large_file(source.size >= u32::MAX as u64);
+                if let Some(pw) = password {
+                    options = options.with_aes_encryption(zip::AesMode::Aes256, pw);
+                }
+                let mut input = match std::fs::File::open(&source.path) {
+                    Ok(f) => f,
+                    Err(_) => {
+                        manifest.skipped.push(source.name.clone());
+                        continue;
+                    }
+                };
+                zip.start_file(source.name.as_str(), options)?;
+                let written = std::io::copy(&mut input, &mut zip)?;
+                manifest.push(source.name.clone(), written, None);
+                progress.entry("Compressing", i, &source.path, written);
+            }
+            zip.finish()?.flush()?;
+        }
+        ArchiveFormat::TarGz => {
+            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            let mut builder = tar::Builder::new(encoder);
+            builder.follow_symlinks(false);
+            for (i, source) in sources.iter().enumerate() {
+                let mut input = match std::fs::File::open(&source.path) {
+                    Ok(f) => f,
+                    Err(_) => {
+                        manifest.skipped.push(source.name.clone());
+                        continue;
+                    }
+                };
+                builder.append_file(&source.name, &mut input)?;
+                manifest.push(source.name.clone(), source.size, None);
+                progress.entry("Compressing", i, &source.path, source.size);
+            }
+            builder.into_inner()?.finish()?.flush()?;
+        }
+    }
+    manifest.total_compressed = std::fs::metadata(archive_path)?.len();
+    Ok(manifest)
+}
+
+/// Validate an in-archive entry name and map it under `output_dir`.
+fn safe_entry_path(output_dir: &Path, name: &str) -> Result<PathBuf, ArchiveViolation> {
+    let path = Path::new(name);
+    let safe = !name.is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if !safe {
+        return Err(ArchiveViolation::PathTraversal {
+            entry: name.to_string(),
+        });
+    }
+    Ok(output_dir.join(path))
+}
+
+fn check_ratio(
+    entry: &str,
+    size: u64,
+    compressed: u64,
+    limits: &ExtractLimits,
+) -> Result<(), ArchiveViolation> {
+    if compressed == 0 {
+        return Ok(());
+    }
+    let ratio = size as f64 / compressed as f64;
+    if ratio > limits.max_ratio {
+        return Err(ArchiveViolation::CompressionRatioExceeded {
+            entry: entry.to_string(),
+            ratio,
+            limit: limits.max_ratio,
+        });
+    }
+    Ok(())
+}
+
+/// Read a zip archive's central directory into a manifest, validating every
+/// entry against the extraction limits.
+fn inspect_zip(
+    archive_path: &Path,
+    archive_name: &str,
+    output_dir: Option<&Path>,
+    limits: &ExtractLimits,
+) -> Result<ArchiveManifest, ArchiveFailure> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(archive_path)?)?;
+    if archive.len() > limits.max_entries {
+        return Err(ArchiveViolation::EntryCountExceeded {
+            limit: limits.max_entries,
+        }
+        .into());
+    }
+    let mut encrypted = false;
+    let mut manifest = ArchiveManifest::new(archive_name, ArchiveFormat::Zip, false);
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i)?;
+        let name = entry.name().to_string();
+        encrypted |= entry.encrypted();
+        if let Some(dir) = output_dir {
+            safe_entry_path(dir, &name)?;
+            if entry.is_symlink() {
+                return Err(ArchiveViolation::LinkEntry { entry: name }.into());
+            }
+            check_ratio(&name, entry.size(), entry.compressed_size(), limits)?;
+        }
+        manifest.total_compressed += entry.compressed_size();
+        manifest.push(name, entry.size(), Some(entry.compressed_size()));
+        if manifest.total_uncompressed > limits.max_total_bytes {
+            return Err(ArchiveViolation::TotalSizeExceeded {
+                limit: limits.max_total_bytes,
+            }
+            .into());
+        }
+    }
+    manifest.encrypted = encrypted;
+    Ok(manifest)
+}
+
+fn extract_zip(
+    archive_path: &Path,
+    output_dir: &Path,
+    password: Option<&str>,
+    manifest: &ArchiveManifest,
+    progress: &Progress,
+) -> Result<(), ArchiveFailure> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(archive_path)?)?;
+    for i in 0..archive.len() {
+        let mut entry = match password {
+            Some(pw) => archive.by_index_decrypt(i, pw.as_bytes())?,
+            None => archive.by_index(i)?,
+        };
+        let name = entry.name().to_string();
+        let out_path = safe_entry_path(output_dir, &name)?;
+        if entry.is_dir() {
+            std::fs::create_dir_all(&out_path)?;
+            continue;
+        }
+        let declared = manifest.entries.get(i).map(|e| e.size).unwrap_or(0);
+        let written = write_bounded(&mut entry, &out_path, declared, &name)?;
+        progress.entry("Extracting", i, &out_path, written);
+    }
+    Ok(())
+}
+
+/// Copy at most `declared` bytes to `out_path`, failing if the source has more.
+fn write_bounded(
+    reader: &mut dyn Read,
+    out_path: &Path,
+    declared: u64,
+    entry: &str,
+) -> Result<u64, ArchiveFailure> {
+    if let Some(parent) = out_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut out = std::fs::File::create(out_path)?;
+    let written = std::io::copy(&mut reader.take(declared + 1), &mut out)?;
+    if written > declared {
+        drop(out);
+        let _ = std::fs::remove_file(out_path);
+        return Err(ArchiveViolation::SizeMismatch {
+            entry: entry.to_string(),
+        }
+        .into());
+    }
+    Ok(written)
+}
+
+fn open_tar(
+    archive_path: &Path,
+) -> std::io::Result<tar::Archive<flate2::read::GzDecoder<std::fs::File>>> {
+    let file = std::fs::File::open(archive_path)?;
+    Ok(tar::Archive::new(flate2::read::GzDecoder::new(file)))
+}
+
+/// Read tar.gz headers into a manifest, validating every entry against the
+/// extraction limits. The ratio guard applies to the archive as a whole since
+/// gzip does not record per-entry compressed sizes.
+fn inspect_tar(
+    archive_path: &Path,
+    archive_name: &str,
+    output_dir: Option<&Path>,
+    limits: &ExtractLimits,
+) -> Result<ArchiveManifest, ArchiveFailure> {
+    let compressed = std::fs::metadata(archive_path)?.len();
+    let mut manifest = ArchiveManifest::new(archive_name, ArchiveFormat::TarGz, false);
+    manifest.total_compressed = compressed;
+    let mut archive = open_tar(archive_path)?;
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+        if manifest.entries.len() >= limits.max_entries {
+            return Err(ArchiveViolation::EntryCountExceeded {
+                limit: limits.max_entries,
+            }
+            .into());
+        }
+        let kind = entry.header().entry_type();
+        if let Some(dir) = output_dir {
+            safe_entry_path(dir, &name)?;
+            if kind.is_symlink() || kind.is_hard_link() {
+                return Err(ArchiveViolation::LinkEntry { entry: name }.into());
+            }
+        }
+        let size = entry.header().size()?;
+        manifest.push(name.clone(), size, None);
+        if manifest.total_uncompressed > limits.max_total_bytes {
+            return Err(ArchiveViolation::TotalSizeExceeded {
+                limit: limits.max_total_bytes,
+            }
+            .into());
+        }
+        if output_dir.is_some() {
+            check_ratio(&name, manifest.total_uncompressed, compressed, limits)?;
+        }
+    }
+    Ok(manifest)
+}
+
+fn extract_tar(
+    archive_path: &Path,
+    output_dir: &Path,
+    manifest: &ArchiveManifest,
+    progress: &Progress,
+) -> Result<(), ArchiveFailure> {
+    let mut archive = open_tar(archive_path)?;
+    for (i, entry) in archive.entries()?.enumerate() {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+        let out_path = safe_entry_path(output_dir, &name)?;
+        let kind = entry.header().entry_type();
+        if kind.is_dir() {
+            std::fs::create_dir_all(&out_path)?;
+            continue;
+        }
+        if !kind.is_file() {
+            continue;
+        }
+        let declared = manifest.entries.get(i).map(|e| e.size).unwrap_or(0);
+        let written = write_bounded(&mut entry, &out_path, declared, &name)?;
+        progress.entry("Extracting", i, &out_path, written);
+    }
+    Ok(())
+}
+
+fn violation_output(violation: ArchiveViolation) -> ToolOutput {
+    let mut output = ToolOutput::error(format!("Archive rejected: {}", violation));
+    output.metadata.insert(
+        "violation".into(),
+        serde_json::to_value(&violation).unwrap_or(Value::Null),
+    );
+    output
+}
+
+fn exec_err(message: impl Into<String>) -> ToolError {
+    ToolError::ExecutionFailed {
+        name: "compress".into(),
+        message: message.into(),
+    }
+}
+
+fn finish(result: Result<ToolOutput, ArchiveFailure>) -> Result<ToolOutput, ToolError> {
+    match result {
+        Ok(output) => Ok(output),
+        Err(ArchiveFailure::Violation(v)) => Ok(violation_output(v)),
+        Err(ArchiveFailure::Io(message)) => Err(exec_err(message)),
     }
 }
 
@@ -26,7 +647,9 @@ impl Tool for CompressTool {
         "compress"
     }
     fn description(&self) -> &str {
-        "Create and extract zip archives. Actions: create_zip, extract_zip, list_zip."
+        "Create, extract, and list zip or tar.gz archives with streaming I/O. \
+         Actions: create, extract, list. Supports include/exclude globs, optional \
+         AES-256 zip encryption (password_ref), and safe extraction limits."
     }
     fn parameters_schema(&self) -> Value {
         json!({
@@ -34,16 +657,42 @@ impl Tool for CompressTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["create_zip", "extract_zip", "list_zip"],
+                    "enum": ["create", "extract", "list", "create_zip", "extract_zip", "list_zip"],
                     "description": "Action to perform"
                 },
-                "archive": { "type": "string", "description": "Path to the zip archive" },
+                "archive": { "type": "string", "description": "Path to the archive (.zip, .tar.gz, .tgz)" },
+                "format": {
+                    "type": "string",
+                    "enum": ["zip", "tar.gz"],
+                    "description": "Archive format (default: inferred from the archive extension)"
+                },
                 "files": {
                     "type": "array",
                     "items": { "type": "string" },
-                    "description": "Files to add to archive (for create_zip)"
+                    "description": "Files or directories to add (for create)"
+                },
+                "include": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Glob patterns a file must match to be added (for create)"
                 },
-                "output_dir": { "type": "string", "description": "Output directory (for extract_zip)" }
+                "exclude": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Glob patterns that exclude files (for create)"
+                },
+                "follow_symlinks": {
+                    "type": "boolean",
+                    "description": "Follow symlinks when collecting files (default: false)"
+                },
+                "password_ref": {
+                    "type": "string",
+                    "description": "Secret reference for the zip passphrase: keychain:<account> or env:<VAR>"
+                },
+                "output_dir": { "type": "string", "description": "Output directory (for extract)" },
+                "max_entries": { "type": "integer", "description": "Maximum entries allowed when extracting (default: 10000)" },
+                "max_total_bytes": { "type": "integer", "description": "Maximum total uncompressed bytes when extracting (default: 16 GiB)" },
+                "max_ratio": { "type": "number", "description": "Maximum compression ratio allowed when extracting (default: 100)" }
             },
             "required": ["action", "archive"]
         })
@@ -52,148 +701,204 @@ impl Tool for CompressTool {
         RiskLevel::Write
     }
     fn timeout(&self) -> Duration {
-        Duration::from_secs(120)
+        Duration::from_secs(600)
     }
 
     async fn execute(&self, args: Value) -> Result<ToolOutput, ToolError> {
         let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("");
-        let archive_str = args.get("archive").and_then(|v| v.as_str()).unwrap_or("");
-        let archive_path = self.workspace.join(archive_str);
+        let archive_str = args
+            .get("archive")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        let archive_path = match self.resolve(&archive_str) {
+            Ok(p) => p,
+            Err(v) => return Ok(violation_output(v)),
+        };
+        let format_arg = args.get("format").and_then(|v| v.as_str());
+        let format = match ArchiveFormat::detect(format_arg, &archive_str) {
+            Some(f) => f,
+            None if action.ends_with("_zip") => ArchiveFormat::Zip,
+            None => {
+                return Err(ToolError::InvalidArguments {
+                    name: "compress".into(),
+                    reason: "cannot determine archive format; use a .zip/.tar.gz/.tgz \
+                             extension or set 'format'"
+                        .into(),
+                });
+            }
+        };
+        let password = resolve_password(&args).map_err(|reason| ToolError::InvalidArguments {
+            name: "compress".into(),
+            reason,
+        })?;
+        let progress = Progress {
+            tx: self.progress_tx.clone(),
+            total: 0,
+        };
 
         match action {
-            "create_zip" => {
-                let files: Vec<String> = args
-                    .get("files")
-                    .and_then(|v| serde_json::from_value(v.clone()).ok())
-                    .unwrap_or_default();
+            "create" | "create_zip" => {
+                let files = string_list(&args, "files");
                 if files.is_empty() {
                     return Ok(ToolOutput::text(
                         "Please provide files to add to the archive.",
                     ));
                 }
-
-                let file = std::fs::File::create(&archive_path).map_err(|e| {
-                    ToolError::ExecutionFailed {
-                        name: "compress".into(),
-                        message: format!("Failed to create archive: {}", e),
-                    }
-                })?;
-                let mut zip = zip::ZipWriter::new(file);
-                let options = zip::write::SimpleFileOptions::default()
-                    .compression_method(zip::CompressionMethod::Deflated);
-
-                let mut added = 0;
-                for file_str in &files {
-                    let file_path = self.workspace.join(file_str);
-                    if !file_path.exists() {
-                        continue;
-                    }
-                    let name = file_path
-                        .strip_prefix(&self.workspace)
-                        .unwrap_or(&file_path)
-                        .to_string_lossy()
-                        .to_string();
-                    if let Ok(mut f) = std::fs::File::open(&file_path) {
-                        let mut buf = Vec::new();
-                        if f.read_to_end(&mut buf).is_ok()
-                            && zip.start_file(&name, options).is_ok()
-                            && zip.write_all(&buf).is_ok()
-                        {
-                            added += 1;
-                        }
+                if password.is_some() && format != ArchiveFormat::Zip {
+                    return Ok(violation_output(ArchiveViolation::EncryptionUnsupported));
+                }
+                let mut roots = Vec::new();
+                for file in &files {
+                    match self.resolve(file) {
+                        Ok(p) if p.exists() => roots.push(p),
+                        Ok(_) => {}
+                        Err(v) => return Ok(violation_output(v)),
                     }
                 }
-                zip.finish().map_err(|e| ToolError::ExecutionFailed {
+                let invalid = |reason: String| ToolError::InvalidArguments {
                     name: "compress".into(),
-                    message: format!("Failed to finalize archive: {}", e),
-                })?;
+                    reason,
+                };
+                let include = build_globset(&string_list(&args, "include")).map_err(invalid)?;
+                let exclude = build_globset(&string_list(&args, "exclude")).map_err(invalid)?;
+                let follow = args
+                    .get("follow_symlinks")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let workspace = self.workspace.clone();
 
-                Ok(ToolOutput::text(format!(
-                    "Created {} with {} files.",
-                    archive_str, added
-                )))
+                let result = tokio::task::spawn_blocking(move || {
+                    let mut manifest =
+                        ArchiveManifest::new(&archive_str, format, password.is_some());
+                    let sources = collect_sources(
+                        &workspace,
+                        &archive_path,
+                        &roots,
+                        include.as_ref(),
+                        exclude.as_ref(),
+                        follow,
+                        &mut manifest.skipped,
+                    );
+                    let progress = Progress {
+                        total: sources.len(),
+                        ..progress
+                    };
+                    create_archive(
+                        &archive_path,
+                        format,
+                        sources,
+                        password.as_deref(),
+                        manifest,
+                        progress,
+                    )
+                    .map(|m| {
+                        let headline =
+                            format!("Created {} with {} files.", m.archive, m.entries.len());
+                        m.into_output(headline)
+                    })
+                })
+                .await
+                .map_err(|e| exec_err(format!("Archive task failed: {}", e)))?;
+                finish(result)
             }
-            "extract_zip" => {
+            "extract" | "extract_zip" => {
                 if !archive_path.exists() {
                     return Ok(ToolOutput::text(format!(
                         "Archive not found: {}",
                         archive_str
                     )));
                 }
-                let output_dir = args
-                    .get("output_dir")
-                    .and_then(|v| v.as_str())
-                    .map(|p| self.workspace.join(p))
-                    .unwrap_or_else(|| self.workspace.clone());
-
-                let file =
-                    std::fs::File::open(&archive_path).map_err(|e| ToolError::ExecutionFailed {
-                        name: "compress".into(),
-                        message: format!("Failed to open archive: {}", e),
-                    })?;
-                let mut archive =
-                    zip::ZipArchive::new(file).map_err(|e| ToolError::ExecutionFailed {
-                        name: "compress".into(),
-                        message: format!("Invalid zip archive: {}", e),
-                    })?;
-
-                let mut extracted = 0;
-                for i in 0..archive.len() {
-                    if let Ok(mut entry) = archive.by_index(i) {
-                        let name = entry.name().to_string();
-                        // Security: prevent path traversal
-                        if name.contains("..") {
-                            continue;
+                let output_dir = match args.get("output_dir").and_then(|v| v.as_str()) {
+                    Some(dir) => match self.resolve(dir) {
+                        Ok(p) => p,
+                        Err(v) => return Ok(violation_output(v)),
+                    },
+                    None => self.workspace.clone(),
+                };
+                let limits = ExtractLimits {
+                    max_entries: args
+                        .get("max_entries")
+                        .and_then(|v| v.as_u64())
+                        .map(|v| v as usize)
+                        .unwrap_or(DEFAULT_MAX_ENTRIES),
+                    max_total_bytes: args
+                        .get("max_total_bytes")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(DEFAULT_MAX_TOTAL_BYTES),
+                    max_ratio: args
+                        .get("max_ratio")
+                        .and_then(|v| v.as_f64())
+                        .unwrap_or(DEFAULT_MAX_RATIO),
+                };
+
+                let result = tokio::task::spawn_blocking(move || {
+                    let manifest = match format {
+                        ArchiveFormat::Zip => {
+                            inspect_zip(&archive_path, &archive_str, Some(&output_dir), &limits)?
+                        }
+                        ArchiveFormat::TarGz => {
+                            inspect_tar(&archive_path, &archive_str, Some(&output_dir), &limits)?
                         }
-                        let out_path = output_dir.join(&name);
-                        if entry.is_dir() {
-                            std::fs::create_dir_all(&out_path).ok();
-                        } else {
-                            if let Some(parent) = out_path.parent() {
-                                std::fs::create_dir_all(parent).ok();
-                            }
-                            let mut buf = Vec::new();
-                            if entry.read_to_end(&mut buf).is_ok()
-                                && std::fs::write(&out_path, &buf).is_ok()
-                            {
-                                extracted += 1;
-                            }
+                    };
+                    let progress = Progress {
+                        total: manifest.entries.len(),
+                        ..progress
+                    };
+                    std::fs::create_dir_all(&output_dir)?;
+                    match format {
+                        ArchiveFormat::Zip => extract_zip(
+                            &archive_path,
+                            &output_dir,
+                            password.as_deref(),
+                            &manifest,
+                            &progress,
+                        )?,
+                        ArchiveFormat::TarGz => {
+                            extract_tar(&archive_path, &output_dir, &manifest, &progress)?
                         }
                     }
-                }
-                Ok(ToolOutput::text(format!(
-                    "Extracted {} files from {}.",
-                    extracted, archive_str
-                )))
+                    let headline = format!(
+                        "Extracted {} entries from {}.",
+                        manifest.entries.len(),
+                        manifest.archive
+                    );
+                    Ok(manifest.into_output(headline))
+                })
+                .await
+                .map_err(|e| exec_err(format!("Archive task failed: {}", e)))?;
+                finish(result)
             }
-            "list_zip" => {
+            "list" | "list_zip" => {
                 if !archive_path.exists() {
                     return Ok(ToolOutput::text(format!(
                         "Archive not found: {}",
                         archive_str
                     )));
                 }
-                let file =
-                    std::fs::File::open(&archive_path).map_err(|e| ToolError::ExecutionFailed {
-                        name: "compress".into(),
-                        message: format!("Failed to open archive: {}", e),
-                    })?;
-                let mut archive =
-                    zip::ZipArchive::new(file).map_err(|e| ToolError::ExecutionFailed {
-                        name: "compress".into(),
-                        message: format!("Invalid zip archive: {}", e),
-                    })?;
-
-                let mut output = format!("Archive: {} ({} entries)\n", archive_str, archive.len());
-                for i in 0..archive.len() {
-                    if let Ok(entry) = archive.by_index_raw(i) {
-                        output.push_str(&format!("  {} ({} bytes)\n", entry.name(), entry.size()));
-                    }
-                }
-                Ok(ToolOutput::text(output))
+                let limits = ExtractLimits {
+                    max_entries: usize::MAX,
+                    max_total_bytes: u64::MAX,
+                    max_ratio: f64::INFINITY,
+                };
+                let result = tokio::task::spawn_blocking(move || {
+                    let manifest = match format {
+                        ArchiveFormat::Zip => {
+                            inspect_zip(&archive_path, &archive_str, None, &limits)?
+                        }
+                        ArchiveFormat::TarGz => {
+                            inspect_tar(&archive_path, &archive_str, None, &limits)?
+                        }
+                    };
+                    let headline = format!("Archive: {}", manifest.archive);
+                    Ok(manifest.into_output(headline))
+                })
+                .await
+                .map_err(|e| exec_err(format!("Archive task failed: {}", e)))?;
+                finish(result)
             }
             _ => Ok(ToolOutput::text(format!(
-                "Unknown action: {}. Use: create_zip, extract_zip, list_zip",
+                "Unknown action: {}. Use: create, extract, list",
                 action
             ))),
         }
@@ -205,17 +910,28 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
-    #[tokio::test]
-    async fn test_compress_create_extract_roundtrip() {
+    fn setup() -> (TempDir, PathBuf, CompressTool) {
         let dir = TempDir::new().unwrap();
         let workspace = dir.path().canonicalize().unwrap();
+        let tool = CompressTool::new(workspace.clone());
+        (dir, workspace, tool)
+    }
+
+    fn violation_kind(output: &ToolOutput) -> String {
+        output.metadata["violation"]["kind"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_compress_create_extract_roundtrip() {
+        let (_dir, workspace, tool) = setup();
 
         // Create test files
         std::fs::write(workspace.join("a.txt"), "Hello A").unwrap();
         std::fs::write(workspace.join("b.txt"), "Hello B").unwrap();
 
-        let tool = CompressTool::new(workspace.clone());
-
         // Create archive
         let result = tool
             .execute(json!({
@@ -256,9 +972,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_compress_nonexistent_archive() {
-        let dir = TempDir::new().unwrap();
-        let workspace = dir.path().canonicalize().unwrap();
-        let tool = CompressTool::new(workspace);
+        let (_dir, _workspace, tool) = setup();
 
         let result = tool
             .execute(json!({"action": "list_zip", "archive": "nope.zip"}))
@@ -273,4 +987,225 @@ mod tests {
         let tool = CompressTool::new(dir.path().to_path_buf());
         assert_eq!(tool.name(), "compress");
     }
+
+    #[tokio::test]
+    async fn test_tar_gz_globs_and_manifest() {
+        let (_dir, workspace, tool) = setup();
+        std::fs::create_dir_all(workspace.join("data/nested")).unwrap();
+        std::fs::write(workspace.join("data/a.csv"), "1,2,3").unwrap();
+        std::fs::write(workspace.join("data/nested/b.csv"), "4,5,6").unwrap();
+        std::fs::write(workspace.join("data/skip.log"), "noise").unwrap();
+        std::fs::write(workspace.join("data/nested/tmp.csv"), "tmp").unwrap();
+
+        let result = tool
+            .execute(json!({
+                "action": "create",
+                "archive": "out/data.tar.gz",
+                "files": ["data"],
+                "include": ["**/*.csv"],
+                "exclude": ["**/tmp.csv"]
+            }))
+            .await
+            .unwrap();
+        let manifest = &result.metadata["manifest"];
+        assert_eq!(manifest["format"], "tar_gz");
+        let paths: Vec<&str> = manifest["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["path"].as_str().unwrap())
+            .collect();
+        assert_eq!(paths, vec!["data/a.csv", "data/nested/b.csv"]);
+        assert_eq!(manifest["total_uncompressed"], 10);
+        assert!(manifest["total_compressed"].as_u64().unwrap() > 0);
+
+        let result = tool
+            .execute(json!({"action": "extract", "archive": "out/data.tar.gz", "output_dir": "restored"}))
+            .await
+            .unwrap();
+        assert!(result.content.contains("Extracted 2"));
+        assert_eq!(
+            std::fs::read_to_string(workspace.join("restored/data/nested/b.csv")).unwrap(),
+            "4,5,6"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_archive_inside_source_dir_not_added_to_itself() {
+        let (_dir, workspace, tool) = setup();
+        std::fs::create_dir_all(workspace.join("data")).unwrap();
+        std::fs::write(workspace.join("data/a.txt"), "alpha").unwrap();
+
+        // The second run finds the first run's archive while walking "data".
+        for _ in 0..2 {
+            let result = tool
+                .execute(
+                    json!({"action": "create", "archive": "data/backup.zip", "files": ["data"]}),
+                )
+                .await
+                .unwrap();
+            let entries = result.metadata["manifest"]["entries"].as_array().unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0]["path"], "data/a.txt");
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlinks_not_followed_by_default() {
+        let (_dir, workspace, tool) = setup();
+        std::fs::create_dir_all(workspace.join("src")).unwrap();
+        std::fs::write(workspace.join("src/real.txt"), "real").unwrap();
+        std::os::unix::fs::symlink(
+            workspace.join("src/real.txt"),
+            workspace.join("src/link.txt"),
+        )
+        .unwrap();
+
+        let result = tool
+            .execute(json!({"action": "create", "archive": "s.zip", "files": ["src"]}))
+            .await
+            .unwrap();
+        let manifest = &result.metadata["manifest"];
+        assert_eq!(manifest["entries"].as_array().unwrap().len(), 1);
+        assert_eq!(manifest["skipped"][0], "src/link.txt");
+    }
+
+    #[tokio::test]
+    async fn test_zip_slip_rejected() {
+        let (_dir, workspace, tool) = setup();
+        let file = std::fs::File::create(workspace.join("evil.zip")).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        zip.start_file("../escape.txt", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"pwned").unwrap();
+        zip.finish().unwrap();
+
+        let result = tool
+            .execute(json!({"action": "extract", "archive": "evil.zip", "output_dir": "out"}))
+            .await
+            .unwrap();
+        assert_eq!(violation_kind(&result), "path_traversal");
+        assert!(!workspace.join("escape.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_ratio_bomb_and_entry_cap() {
+        let (_dir, workspace, tool) = setup();
+        std::fs::write(workspace.join("zeros.bin"), vec![0u8; 1024 * 1024]).unwrap();
+        std::fs::write(workspace.join("small.txt"), "x").unwrap();
+        tool.execute(
+            json!({"action": "create", "archive": "bomb.zip", "files": ["zeros.bin", "small.txt"]}),
+        )
+        .await
+        .unwrap();
+
+        let result = tool
+            .execute(json!({"action": "extract", "archive": "bomb.zip", "output_dir": "out"}))
+            .await
+            .unwrap();
+        assert_eq!(violation_kind(&result), "compression_ratio_exceeded");
+        assert!(!workspace.join("out/zeros.bin").exists());
+
+        let result = tool
+            .execute(json!({
+                "action": "extract",
+                "archive": "bomb.zip",
+                "output_dir": "out",
+                "max_entries": 1
+            }))
+            .await
+            .unwrap();
+        assert_eq!(violation_kind(&result), "entry_count_exceeded");
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_zip_roundtrip() {
+        let (_dir, workspace, tool) = setup();
+        std::fs::write(workspace.join("secret.txt"), "classified").unwrap();
+        // SAFETY: test-only env var with a unique name.
+        unsafe { std::env::set_var("RUSTANT_TEST_COMPRESS_PASS", "hunter2") };
+
+        let result = tool
+            .execute(json!({
+                "action": "create",
+                "archive": "sealed.zip",
+                "files": ["secret.txt"],
+                "password_ref": "env:RUSTANT_TEST_COMPRESS_PASS"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.metadata["manifest"]["encrypted"], true);
+
+        let result = tool
+            .execute(json!({
+                "action": "extract",
+                "archive": "sealed.zip",
+                "output_dir": "opened",
+                "password_ref": "env:RUSTANT_TEST_COMPRESS_PASS"
+            }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("Extracted 1"));
+        assert_eq!(
+            std::fs::read_to_string(workspace.join("opened/secret.txt")).unwrap(),
+            "classified"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inline_passphrase_and_tar_encryption_rejected() {
+        let (_dir, workspace, tool) = setup();
+        std::fs::write(workspace.join("a.txt"), "a").unwrap();
+        let err = tool
+            .execute(json!({"action": "create", "archive": "a.zip", "files": ["a.txt"], "password_ref": "plaintext"}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments { .. }));
+
+        // SAFETY: test-only env var with a unique name.
+        unsafe { std::env::set_var("RUSTANT_TEST_COMPRESS_TAR_PASS", "x") };
+        let result = tool
+            .execute(json!({
+                "action": "create",
+                "archive": "a.tar.gz",
+                "files": ["a.txt"],
+                "password_ref": "env:RUSTANT_TEST_COMPRESS_TAR_PASS"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(violation_kind(&result), "encryption_unsupported");
+    }
+
+    #[tokio::test]
+    async fn test_progress_updates_streamed() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        std::fs::write(workspace.join("a.txt"), "a").unwrap();
+        std::fs::write(workspace.join("b.txt"), "b").unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let tool = CompressTool::with_progress(workspace, tx);
+        tool.execute(
+            json!({"action": "create", "archive": "p.tar.gz", "files": ["a.txt", "b.txt"]}),
+        )
+        .await
+        .unwrap();
+        let mut stages = Vec::new();
+        while let Ok(update) = rx.try_recv() {
+            if let ProgressUpdate::ToolProgress { stage, .. } = update {
+                stages.push(stage);
+            }
+        }
+        assert_eq!(stages, vec!["Compressing 1/2", "Compressing 2/2"]);
+    }
+
+    #[tokio::test]
+    async fn test_archive_path_outside_workspace_rejected() {
+        let (_dir, _workspace, tool) = setup();
+        let result = tool
+            .execute(json!({"action": "list", "archive": "../other.zip"}))
+            .await
+            .unwrap();
+        assert_eq!(violation_kind(&result), "outside_workspace");
+    }
 }
@@ -1,21 +1,55 @@
 //! macOS Contacts.app tool — search, read, and manage contacts via AppleScript.
 //!
 //! Provides full access to the macOS address book for searching contacts,
-//! reading details, creating new contacts, and listing groups.
+//! reading details, creating new contacts, and listing groups. Contacts found
+//! by a search are recorded in the workspace person graph so they link up with
+//! the same people on channels.
 //! macOS only.
 
 use crate::macos::{require_str, run_osascript, sanitize_applescript_string};
 use crate::registry::Tool;
 use async_trait::async_trait;
 use rustant_core::error::ToolError;
+use rustant_core::people::{PersonGraph, PersonObservation};
 use rustant_core::types::{RiskLevel, ToolOutput};
 use serde_json::json;
+use std::path::PathBuf;
 use std::time::Duration;
 use tracing::debug;
 
 const TOOL_NAME: &str = "macos_contacts";
 
-pub struct MacosContactsTool;
+#[derive(Default)]
+pub struct MacosContactsTool {
+    /// Workspace whose person graph search results are recorded in.
+    workspace: Option<PathBuf>,
+}
+
+impl MacosContactsTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self {
+            workspace: Some(workspace),
+        }
+    }
+
+    /// Record contact cards from search output in the person graph.
+    fn record_cards(&self, output: &str) {
+        let Some(workspace) = &self.workspace else {
+            return;
+        };
+        let cards: Vec<PersonObservation> = output.lines().filter_map(parse_card).collect();
+        if cards.is_empty() {
+            return;
+        }
+        let mut graph = PersonGraph::load(workspace);
+        for card in cards {
+            graph.observe(card);
+        }
+        if let Err(e) = graph.save(workspace) {
+            debug!(error = %e, "Failed to record contacts in person graph");
+        }
+    }
+}
 
 #[async_trait]
 impl Tool for MacosContactsTool {
@@ -74,7 +108,11 @@ impl Tool for MacosContactsTool {
         let action = require_str(&args, "action", TOOL_NAME)?;
 
         match action {
-            "search" => execute_search(&args).await,
+            "search" => {
+                let output = execute_search(&args).await?;
+                self.record_cards(&output.content);
+                Ok(output)
+            }
             "get_details" => execute_get_details(&args).await,
             "create" => execute_create(&args).await,
             "list_groups" => execute_list_groups().await,
@@ -336,6 +374,34 @@ end tell
     Ok(ToolOutput::text(result))
 }
 
+/// Parse a search result line, `Name <email> (phone) @ Company`, into an
+/// observation of a contacts card.
+fn parse_card(line: &str) -> Option<PersonObservation> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with("No contacts found") {
+        return None;
+    }
+    let line = line.split(" @ ").next().unwrap_or(line);
+    let name_end = line.find(['<', '(']).unwrap_or(line.len());
+    let name = line[..name_end].trim();
+    if name.is_empty() {
+        return None;
+    }
+    let mut card = PersonObservation::from_handle("contacts", name).with_name(name);
+    let between = |open: char, close: char| {
+        let start = line.find(open)? + 1;
+        let end = start + line[start..].find(close)?;
+        Some(line[start..end].trim().to_string())
+    };
+    if let Some(email) = between('<', '>') {
+        card = card.with_email(email);
+    }
+    if let Some(phone) = between('(', ')') {
+        card = card.with_phone(phone);
+    }
+    Some(card)
+}
+
 // ── Tests ─────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -344,25 +410,25 @@ mod tests {
 
     #[test]
     fn test_contacts_name() {
-        let tool = MacosContactsTool;
+        let tool = MacosContactsTool::default();
         assert_eq!(tool.name(), "macos_contacts");
     }
 
     #[test]
     fn test_contacts_risk_level() {
-        let tool = MacosContactsTool;
+        let tool = MacosContactsTool::default();
         assert_eq!(tool.risk_level(), RiskLevel::Write);
     }
 
     #[test]
     fn test_contacts_timeout() {
-        let tool = MacosContactsTool;
+        let tool = MacosContactsTool::default();
         assert_eq!(tool.timeout(), Duration::from_secs(15));
     }
 
     #[test]
     fn test_contacts_schema() {
-        let tool = MacosContactsTool;
+        let tool = MacosContactsTool::default();
         let schema = tool.parameters_schema();
         let props = schema["properties"].as_object().unwrap();
         assert!(props.contains_key("action"));
@@ -374,9 +440,22 @@ mod tests {
         assert!(props.contains_key("phone"));
     }
 
+    #[test]
+    fn test_parse_card() {
+        let card = parse_card("Priya Shah <priya@example.com> (+1 555 010 0199) @ Acme").unwrap();
+        assert_eq!(card.name.as_deref(), Some("Priya Shah"));
+        assert_eq!(card.emails, vec!["priya@example.com"]);
+        assert_eq!(card.phones, vec!["+1 555 010 0199"]);
+        assert_eq!(card.handle.unwrap().id, "Priya Shah");
+
+        let bare = parse_card("Sam").unwrap();
+        assert!(bare.emails.is_empty() && bare.phones.is_empty());
+        assert!(parse_card("No contacts found matching 'x'.").is_none());
+    }
+
     #[tokio::test]
     async fn test_contacts_missing_action() {
-        let tool = MacosContactsTool;
+        let tool = MacosContactsTool::default();
         let result = tool.execute(json!({})).await;
         assert!(result.is_err());
         match result.unwrap_err() {
@@ -390,7 +469,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_contacts_invalid_action() {
-        let tool = MacosContactsTool;
+        let tool = MacosContactsTool::default();
         let result = tool.execute(json!({"action": "bad"})).await;
         assert!(result.is_err());
         match result.unwrap_err() {
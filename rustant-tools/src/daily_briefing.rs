@@ -1,7 +1,8 @@
-//! Daily briefing tool — aggregates calendar, reminders, weather, and system
-//! status into a structured note in Notes.app.
+//! Daily briefing tool — aggregates calendar, reminders, weather, system
+//! status, and job application follow-ups into a structured note in Notes.app.
 //!
-//! macOS only.
+//! macOS only. The cross-platform, configurable briefing is the `briefing`
+//! tool in [`crate::briefing`].
 
 use crate::macos::{run_command, run_osascript, sanitize_applescript_string};
 use crate::registry::Tool;
@@ -10,6 +11,7 @@ use chrono::Utc;
 use rustant_core::error::ToolError;
 use rustant_core::types::{RiskLevel, ToolOutput};
 use serde_json::json;
+use std::path::PathBuf;
 use std::time::Duration;
 use tracing::debug;
 
@@ -159,6 +161,29 @@ end tell"#;
     run_osascript(script).await
 }
 
+/// Fetch calendar events from now through the next `days` days, for the
+/// cross-platform briefing's calendar section.
+pub(crate) async fn get_upcoming_events(days: i64) -> Result<String, String> {
+    let script = format!(
+        r#"tell application "Calendar"
+    set output to ""
+    set startDate to current date
+    set endDate to startDate + ({days} * days)
+    repeat with cal in calendars
+        set calEvents to (every event of cal whose start date >= startDate and start date < endDate)
+        repeat with evt in calEvents
+            set output to output & "- " & (summary of evt) & " at " & (start date of evt as string) & " (" & (name of cal) & ")" & linefeed
+        end repeat
+    end repeat
+    if output is "" then
+        return "No events scheduled."
+    end if
+    return output
+end tell"#
+    );
+    run_osascript(&script).await
+}
+
 /// Save briefing to Notes.app.
 async fn save_briefing_note(title: &str, body: &str, folder: &str) -> Result<String, String> {
     let title_safe = sanitize_applescript_string(title);
@@ -187,7 +212,15 @@ end tell"#
     run_osascript(&script).await
 }
 
-pub struct MacosDailyBriefingTool;
+pub struct MacosDailyBriefingTool {
+    workspace: PathBuf,
+}
+
+impl MacosDailyBriefingTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
 
 #[async_trait]
 impl Tool for MacosDailyBriefingTool {
@@ -268,6 +301,12 @@ impl Tool for MacosDailyBriefingTool {
                     reminders.replace('\n', "<br>")
                 ));
 
+                // Job applications awaiting follow-up
+                let career = crate::career_intel::follow_up_briefing_line(&self.workspace);
+                if let Some(ref line) = career {
+                    sections.push(format!("<h2>Job Search</h2><p>{}</p>", line));
+                }
+
                 // Weather
                 if include_weather {
                     let weather = get_weather(location)
@@ -306,9 +345,13 @@ impl Tool for MacosDailyBriefingTool {
                     "=== Morning Briefing - {date_str} ===\n\n\
                      Schedule:\n{events}\n\
                      Reminders:\n{reminders}\n\
+                     {career_section}\
                      {weather_section}\
                      {system_section}\
                      {save_result}",
+                    career_section = career
+                        .map(|line| format!("Job search: {line}\n\n"))
+                        .unwrap_or_default(),
                     weather_section = if include_weather {
                         format!(
                             "Weather: {}\n\n",
@@ -399,6 +442,10 @@ impl Tool for MacosDailyBriefingTool {
                     .unwrap_or_else(|e| format!("Could not fetch reminders: {e}"));
                 parts.push(format!("Reminders:\n{reminders}"));
 
+                if let Some(line) = crate::career_intel::follow_up_briefing_line(&self.workspace) {
+                    parts.push(format!("Job search: {line}"));
+                }
+
                 Ok(ToolOutput::text(parts.join("\n\n")))
             }
 
@@ -427,7 +474,7 @@ mod tests {
 
     #[test]
     fn test_schema_has_required_fields() {
-        let tool = MacosDailyBriefingTool;
+        let tool = MacosDailyBriefingTool::new(std::env::temp_dir());
         let schema = tool.parameters_schema();
         assert_eq!(schema["type"], "object");
         assert!(
@@ -447,7 +494,7 @@ mod tests {
 
     #[test]
     fn test_tool_metadata() {
-        let tool = MacosDailyBriefingTool;
+        let tool = MacosDailyBriefingTool::new(std::env::temp_dir());
         assert_eq!(tool.name(), "macos_daily_briefing");
         assert!(tool.description().contains("briefing"));
         assert_eq!(tool.risk_level(), RiskLevel::Write);
@@ -463,7 +510,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_invalid_action_returns_error() {
-        let tool = MacosDailyBriefingTool;
+        let tool = MacosDailyBriefingTool::new(std::env::temp_dir());
         let args = json!({"action": "invalid"});
         let result = tool.execute(args).await;
         assert!(result.is_err());
@@ -471,7 +518,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_missing_action_returns_error() {
-        let tool = MacosDailyBriefingTool;
+        let tool = MacosDailyBriefingTool::new(std::env::temp_dir());
         let args = json!({});
         let result = tool.execute(args).await;
         assert!(result.is_err());
@@ -0,0 +1,894 @@
+//! Structured summaries of unified diffs.
+//!
+//! [`DiffSummary::from_diff`] turns `git diff` output into per-file change
+//! types, the symbols each hunk touches, hunk-level risk annotations and
+//! aggregate stats. Symbols come from the indexer's definition extraction, so
+//! a hunk reads as "modified function `resolve_auth`" rather than a line
+//! range. `git_diff` uses this for `summarize: true`, and the `code_review`
+//! and `pr_review` workflows reach it through `git_diff`.
+//!
+//! Diffs too large to analyse hunk by hunk degrade to file-level summaries
+//! with a note, and the rendered text lists a bounded number of files.
+
+use rustant_core::indexer::{Symbol, SymbolKind, extract_symbols, language_for_path};
+use rustant_core::sanitize::redact_secrets;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Diffs larger than this are summarised per file only.
+pub const MAX_DETAILED_DIFF_BYTES: usize = 2 * 1024 * 1024;
+/// Diffs touching more files than this are summarised per file only.
+pub const MAX_DETAILED_FILES: usize = 300;
+/// Files listed individually in the rendered summary.
+const MAX_RENDERED_FILES: usize = 60;
+/// Symbols and hunks listed per file in the rendered summary.
+const MAX_RENDERED_ITEMS: usize = 10;
+
+/// Substrings of a changed line that suggest error handling.
+const ERROR_MARKERS: &[&str] = &[
+    "Err(",
+    "Result<",
+    ".unwrap(",
+    ".expect(",
+    "panic!",
+    ")?",
+    ".map_err(",
+    "catch",
+    "except ",
+    "except:",
+    "raise ",
+    "throw ",
+    "rescue",
+    "err != nil",
+    "errors.New",
+];
+
+/// How a file changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeType {
+    Added,
+    Deleted,
+    #[default]
+    Modified,
+    Renamed,
+    Binary,
+}
+
+impl ChangeType {
+    fn marker(self) -> char {
+        match self {
+            Self::Added => 'A',
+            Self::Deleted => 'D',
+            Self::Modified => 'M',
+            Self::Renamed => 'R',
+            Self::Binary => 'B',
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Deleted => "deleted",
+            Self::Modified => "modified",
+            Self::Renamed => "renamed",
+            Self::Binary => "binary",
+        }
+    }
+}
+
+/// What kind of file changed, judged by its path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileCategory {
+    Source,
+    Test,
+    Config,
+    Docs,
+    Other,
+}
+
+impl FileCategory {
+    /// Categorise a workspace-relative path.
+    pub fn of(path: &str) -> Self {
+        let lower = path.to_lowercase();
+        let name = lower.rsplit('/').next().unwrap_or(&lower);
+        let ext = name.rsplit_once('.').map(|(_, e)| e).unwrap_or("");
+        let is_test = lower.starts_with("tests/")
+            || lower.contains("/tests/")
+            || lower.contains("/test/")
+            || lower.contains("__tests__/")
+            || name.starts_with("test_")
+            || name.contains("_test.")
+            || name.contains(".test.")
+            || name.contains(".spec.");
+        if is_test {
+            Self::Test
+        } else if matches!(
+            ext,
+            "toml" | "yaml" | "yml" | "json" | "ini" | "cfg" | "conf" | "properties" | "env"
+        ) || name.starts_with(".env")
+            || name == "dockerfile"
+            || is_secret_path(&lower)
+        {
+            Self::Config
+        } else if matches!(ext, "md" | "rst" | "adoc" | "txt") || lower.starts_with("docs/") {
+            Self::Docs
+        } else if language_for_path(path).is_some() {
+            Self::Source
+        } else {
+            Self::Other
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Source => "source",
+            Self::Test => "test",
+            Self::Config => "config",
+            Self::Docs => "docs",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// A review-relevant property of a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskFlag {
+    /// Changed lines return, propagate or handle errors.
+    ErrorHandling,
+    /// A public definition's signature was added, removed or changed.
+    PublicApi,
+    /// Test code changed.
+    Tests,
+    /// A configuration file changed.
+    Config,
+    /// A secrets file changed, or a changed line looks like a credential.
+    Secrets,
+}
+
+impl RiskFlag {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ErrorHandling => "error handling",
+            Self::PublicApi => "public API",
+            Self::Tests => "tests",
+            Self::Config => "configuration",
+            Self::Secrets => "secrets",
+        }
+    }
+}
+
+/// How a symbol changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolChangeKind {
+    Added,
+    Removed,
+    /// The body or the signature changed.
+    Modified,
+}
+
+/// A symbol touched by a diff.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolChange {
+    pub kind: SymbolKind,
+    pub name: String,
+    pub change: SymbolChangeKind,
+}
+
+impl SymbolChange {
+    fn describe(&self) -> String {
+        let verb = match self.change {
+            SymbolChangeKind::Added => "added",
+            SymbolChangeKind::Removed => "removed",
+            SymbolChangeKind::Modified => "modified",
+        };
+        format!("{} {} `{}`", verb, self.kind, self.name)
+    }
+}
+
+/// One hunk of a file's diff.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HunkSummary {
+    pub old_start: usize,
+    pub new_start: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    pub symbols: Vec<SymbolChange>,
+    pub risks: BTreeSet<RiskFlag>,
+}
+
+/// One changed file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSummary {
+    pub path: String,
+    /// Previous path of a renamed file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub change: ChangeType,
+    pub category: FileCategory,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Symbols touched across all hunks. Empty in file-level summaries.
+    pub symbols: Vec<SymbolChange>,
+    /// Empty in file-level summaries.
+    pub hunks: Vec<HunkSummary>,
+    pub risks: BTreeSet<RiskFlag>,
+}
+
+/// Totals across every file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub files: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Files per change type.
+    pub changes: BTreeMap<ChangeType, usize>,
+    /// Files per risk flag.
+    pub risks: BTreeMap<RiskFlag, usize>,
+}
+
+/// Structured summary of a unified diff.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiffSummary {
+    pub files: Vec<FileSummary>,
+    pub stats: DiffStats,
+    /// False when the diff was too large for hunk-level analysis.
+    pub detailed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl DiffSummary {
+    /// Summarise `diff`. `new_content` returns the post-change content of a
+    /// path, used to find the symbol enclosing each hunk; without it only
+    /// definitions on changed lines and hunk headers are recognised.
+    pub fn from_diff(diff: &str, new_content: impl Fn(&str) -> Option<String>) -> Self {
+        let raw = parse_diff(diff);
+        let detailed = diff.len() <= MAX_DETAILED_DIFF_BYTES && raw.len() <= MAX_DETAILED_FILES;
+        let note = (!detailed).then(|| {
+            format!(
+                "Diff too large for hunk-level analysis ({} files, {} KB); showing file-level summaries. Pass `path` to inspect one file.",
+                raw.len(),
+                diff.len() / 1024
+            )
+        });
+
+        let files: Vec<FileSummary> = raw
+            .into_iter()
+            .map(|file| {
+                let content = (detailed && file.change != ChangeType::Deleted)
+                    .then(|| new_content(&file.path))
+                    .flatten();
+                summarize_file(file, detailed, content.as_deref())
+            })
+            .collect();
+
+        let mut stats = DiffStats {
+            files: files.len(),
+            ..Default::default()
+        };
+        for file in &files {
+            stats.additions += file.additions;
+            stats.deletions += file.deletions;
+            *stats.changes.entry(file.change).or_default() += 1;
+            for risk in &file.risks {
+                *stats.risks.entry(*risk).or_default() += 1;
+            }
+        }
+
+        Self {
+            files,
+            stats,
+            detailed,
+            note,
+        }
+    }
+
+    /// Paths whose post-change content [`from_diff`](Self::from_diff) would
+    /// ask for: changed text files, when the diff is small enough to analyse.
+    pub fn content_paths(diff: &str) -> Vec<String> {
+        if diff.len() > MAX_DETAILED_DIFF_BYTES {
+            return Vec::new();
+        }
+        let raw = parse_diff(diff);
+        if raw.len() > MAX_DETAILED_FILES {
+            return Vec::new();
+        }
+        raw.into_iter()
+            .filter(|f| !matches!(f.change, ChangeType::Deleted | ChangeType::Binary))
+            .map(|f| f.path)
+            .collect()
+    }
+
+    /// Human-readable summary, bounded in length regardless of diff size.
+    pub fn render(&self) -> String {
+        let stats = &self.stats;
+        let changes: Vec<String> = stats
+            .changes
+            .iter()
+            .map(|(change, n)| format!("{} {}", n, change.as_str()))
+            .collect();
+        let mut out = format!(
+            "Diff summary: {} file{} changed, +{} -{} ({})\n",
+            stats.files,
+            if stats.files == 1 { "" } else { "s" },
+            stats.additions,
+            stats.deletions,
+            changes.join(", ")
+        );
+        if !stats.risks.is_empty() {
+            let risks: Vec<String> = stats
+                .risks
+                .iter()
+                .map(|(risk, n)| format!("{} ({} file{})", risk.label(), n, plural(*n)))
+                .collect();
+            out.push_str(&format!("Risks: {}\n", risks.join(", ")));
+        }
+        if let Some(note) = &self.note {
+            out.push_str(&format!("Note: {}\n", note));
+        }
+
+        // Riskiest and largest files first.
+        let mut order: Vec<&FileSummary> = self.files.iter().collect();
+        order.sort_by_key(|f| {
+            (
+                std::cmp::Reverse(f.risks.len()),
+                std::cmp::Reverse(f.additions + f.deletions),
+            )
+        });
+        for file in order.iter().take(MAX_RENDERED_FILES) {
+            out.push('\n');
+            let path = match &file.old_path {
+                Some(old) => format!("{} -> {}", old, file.path),
+                None => file.path.clone(),
+            };
+            out.push_str(&format!(
+                "{} {} (+{} -{}, {})",
+                file.change.marker(),
+                path,
+                file.additions,
+                file.deletions,
+                file.category.as_str()
+            ));
+            if !file.risks.is_empty() {
+                out.push_str(&format!(" [{}]", labels(&file.risks)));
+            }
+            out.push('\n');
+            for symbol in file.symbols.iter().take(MAX_RENDERED_ITEMS) {
+                out.push_str(&format!("    {}\n", symbol.describe()));
+            }
+            if file.symbols.len() > MAX_RENDERED_ITEMS {
+                out.push_str(&format!(
+                    "    ... {} more symbols\n",
+                    file.symbols.len() - MAX_RENDERED_ITEMS
+                ));
+            }
+            let risky = file.hunks.iter().filter(|h| !h.risks.is_empty());
+            for hunk in risky.take(MAX_RENDERED_ITEMS) {
+                let location = match hunk.symbols.first() {
+                    Some(symbol) => format!("in {} `{}`", symbol.kind, symbol.name),
+                    None => format!("at line {}", hunk.new_start),
+                };
+                out.push_str(&format!(
+                    "    hunk @{} {} (+{} -{}): {}\n",
+                    hunk.new_start,
+                    location,
+                    hunk.additions,
+                    hunk.deletions,
+                    labels(&hunk.risks)
+                ));
+            }
+        }
+        if order.len() > MAX_RENDERED_FILES {
+            out.push_str(&format!(
+                "\n... {} more files (full details in the `diff_summary` metadata)\n",
+                order.len() - MAX_RENDERED_FILES
+            ));
+        }
+        out
+    }
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 { "" } else { "s" }
+}
+
+fn labels(risks: &BTreeSet<RiskFlag>) -> String {
+    risks
+        .iter()
+        .map(|r| r.label())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn is_secret_path(lower: &str) -> bool {
+    let name = lower.rsplit('/').next().unwrap_or(lower);
+    name.starts_with(".env")
+        || name.contains("secret")
+        || name.contains("credential")
+        || name.starts_with("id_rsa")
+        || name.starts_with("id_ed25519")
+        || [".pem", ".key", ".p12", ".pfx", ".keystore"]
+            .iter()
+            .any(|ext| name.ends_with(ext))
+}
+
+// ── Parsing ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Default)]
+struct RawFile {
+    path: String,
+    old_path: Option<String>,
+    change: ChangeType,
+    hunks: Vec<RawHunk>,
+}
+
+#[derive(Debug)]
+struct RawHunk {
+    old_start: usize,
+    new_start: usize,
+    /// Text after the second `@@`, usually the enclosing definition.
+    context: String,
+    /// `(new-side line, text)` of added lines.
+    added: Vec<(usize, String)>,
+    /// `(new-side position, text)` of removed lines.
+    removed: Vec<(usize, String)>,
+}
+
+/// Strip git's `a/` / `b/` prefix; `None` for `/dev/null`.
+fn diff_path(raw: &str) -> Option<String> {
+    let raw = raw.trim_end_matches('\t').trim();
+    let raw = raw.trim_matches('"');
+    if raw == "/dev/null" {
+        return None;
+    }
+    Some(
+        raw.strip_prefix("a/")
+            .or_else(|| raw.strip_prefix("b/"))
+            .unwrap_or(raw)
+            .to_string(),
+    )
+}
+
+/// `@@ -12,5 +14,7 @@ context` → `(12, 5, 14, 7, context)`.
+fn parse_hunk_header(line: &str) -> Option<(usize, usize, usize, usize, String)> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, context) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let range = |r: &str| -> Option<(usize, usize)> {
+        match r.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((r.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_len) = range(old)?;
+    let (new_start, new_len) = range(new)?;
+    Some((
+        old_start,
+        old_len,
+        new_start,
+        new_len,
+        context.trim().to_string(),
+    ))
+}
+
+fn parse_diff(diff: &str) -> Vec<RawFile> {
+    let mut files: Vec<RawFile> = Vec::new();
+    // Lines still expected in the current hunk, old side and new side.
+    let (mut old_left, mut new_left) = (0usize, 0usize);
+    let mut new_line = 0usize;
+
+    for line in diff.lines() {
+        if old_left > 0 || new_left > 0 {
+            let Some(hunk) = files.last_mut().and_then(|f| f.hunks.last_mut()) else {
+                old_left = 0;
+                new_left = 0;
+                continue;
+            };
+            // Some tools strip the space from blank context lines.
+            let first = line.chars().next().unwrap_or(' ');
+            let text = line.get(1..).unwrap_or("").to_string();
+            match first {
+                '+' => {
+                    hunk.added.push((new_line, text));
+                    new_line += 1;
+                    new_left = new_left.saturating_sub(1);
+                }
+                '-' => {
+                    hunk.removed.push((new_line, text));
+                    old_left = old_left.saturating_sub(1);
+                }
+                ' ' => {
+                    new_line += 1;
+                    new_left = new_left.saturating_sub(1);
+                    old_left = old_left.saturating_sub(1);
+                }
+                // "\ No newline at end of file"
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            // Paths are refined by the ---/+++ and rename lines that follow.
+            let path = rest
+                .rsplit_once(" b/")
+                .map(|(_, b)| b.to_string())
+                .unwrap_or_else(|| rest.to_string());
+            files.push(RawFile {
+                path,
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if line.starts_with("new file mode") {
+            file.change = ChangeType::Added;
+        } else if line.starts_with("deleted file mode") {
+            file.change = ChangeType::Deleted;
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            file.old_path = Some(from.to_string());
+            file.change = ChangeType::Renamed;
+        } else if let Some(to) = line.strip_prefix("rename to ") {
+            file.path = to.to_string();
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.change = ChangeType::Binary;
+        } else if let Some(old) = line.strip_prefix("--- ") {
+            if diff_path(old).is_none() {
+                file.change = ChangeType::Added;
+            }
+        } else if let Some(new) = line.strip_prefix("+++ ") {
+            match diff_path(new) {
+                Some(path) => file.path = path,
+                None => file.change = ChangeType::Deleted,
+            }
+        } else if let Some((old_start, old_len, new_start, new_len, context)) =
+            parse_hunk_header(line)
+        {
+            old_left = old_len;
+            new_left = new_len;
+            new_line = new_start;
+            file.hunks.push(RawHunk {
+                old_start,
+                new_start,
+                context,
+                added: Vec::new(),
+                removed: Vec::new(),
+            });
+        }
+    }
+    files
+}
+
+// ── Analysis ────────────────────────────────────────────────────────
+
+fn summarize_file(file: RawFile, detailed: bool, content: Option<&str>) -> FileSummary {
+    let category = FileCategory::of(&file.path);
+    let additions = file.hunks.iter().map(|h| h.added.len()).sum();
+    let deletions = file.hunks.iter().map(|h| h.removed.len()).sum();
+
+    let mut risks = BTreeSet::new();
+    match category {
+        FileCategory::Test => {
+            risks.insert(RiskFlag::Tests);
+        }
+        FileCategory::Config => {
+            risks.insert(RiskFlag::Config);
+        }
+        _ => {}
+    }
+    if is_secret_path(&file.path.to_lowercase()) {
+        risks.insert(RiskFlag::Secrets);
+    }
+
+    let mut symbols: Vec<SymbolChange> = Vec::new();
+    let mut hunks = Vec::new();
+    if detailed {
+        let defined = content
+            .map(|c| extract_symbols(c, &file.path))
+            .unwrap_or_default();
+        for raw in &file.hunks {
+            let hunk = summarize_hunk(raw, &file.path, category, &defined);
+            for symbol in &hunk.symbols {
+                match symbols
+                    .iter_mut()
+                    .find(|s| s.kind == symbol.kind && s.name == symbol.name)
+                {
+                    // A definition added or removed in one hunk stays that way.
+                    Some(existing) if existing.change == SymbolChangeKind::Modified => {
+                        existing.change = symbol.change
+                    }
+                    Some(_) => {}
+                    None => symbols.push(symbol.clone()),
+                }
+            }
+            risks.extend(hunk.risks.iter().copied());
+            hunks.push(hunk);
+        }
+    }
+
+    FileSummary {
+        path: file.path,
+        old_path: file.old_path,
+        change: file.change,
+        category,
+        additions,
+        deletions,
+        symbols,
+        hunks,
+        risks,
+    }
+}
+
+fn summarize_hunk(
+    raw: &RawHunk,
+    path: &str,
+    category: FileCategory,
+    defined: &[Symbol],
+) -> HunkSummary {
+    let joined = |lines: &[(usize, String)]| {
+        lines
+            .iter()
+            .map(|(_, text)| text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let added_defs = extract_symbols(&joined(&raw.added), path);
+    let removed_defs = extract_symbols(&joined(&raw.removed), path);
+
+    let mut symbols: Vec<SymbolChange> = Vec::new();
+    let mut push = |kind: SymbolKind, name: &str, change: SymbolChangeKind| {
+        if !symbols.iter().any(|s| s.kind == kind && s.name == name) {
+            symbols.push(SymbolChange {
+                kind,
+                name: name.to_string(),
+                change,
+            });
+        }
+    };
+    for def in &added_defs {
+        let change = if removed_defs
+            .iter()
+            .any(|r| r.kind == def.kind && r.name == def.name)
+        {
+            SymbolChangeKind::Modified
+        } else {
+            SymbolChangeKind::Added
+        };
+        push(def.kind, &def.name, change);
+    }
+    for def in &removed_defs {
+        push(def.kind, &def.name, SymbolChangeKind::Removed);
+    }
+    // Body changes: the definition enclosing each changed line.
+    let mut in_tests = false;
+    let positions = raw.added.iter().chain(&raw.removed).map(|(line, _)| *line);
+    let mut enclosing: Vec<&Symbol> = Vec::new();
+    for line in positions {
+        let Some(symbol) = defined.iter().rev().find(|s| s.line <= line) else {
+            continue;
+        };
+        if !enclosing.iter().any(|s| std::ptr::eq(*s, symbol)) {
+            enclosing.push(symbol);
+        }
+    }
+    enclosing.sort_by_key(|s| s.line);
+    for symbol in enclosing {
+        in_tests |= is_test_symbol(symbol);
+        push(symbol.kind, &symbol.name, SymbolChangeKind::Modified);
+    }
+    if defined.is_empty()
+        && let Some(context) = extract_symbols(&raw.context, path).into_iter().next()
+    {
+        in_tests |= is_test_symbol(&context);
+        push(context.kind, &context.name, SymbolChangeKind::Modified);
+    }
+
+    let mut risks = BTreeSet::new();
+    let changed = || {
+        raw.added
+            .iter()
+            .chain(&raw.removed)
+            .map(|(_, t)| t.as_str())
+    };
+    if changed().any(|t| ERROR_MARKERS.iter().any(|m| t.contains(m))) {
+        risks.insert(RiskFlag::ErrorHandling);
+    }
+    let ext = path.rsplit_once('.').map(|(_, e)| e).unwrap_or("");
+    if added_defs
+        .iter()
+        .chain(&removed_defs)
+        .any(|d| is_public(ext, &d.signature, &d.name))
+    {
+        risks.insert(RiskFlag::PublicApi);
+    }
+    if category == FileCategory::Test
+        || in_tests
+        || changed().any(|t| {
+            let t = t.trim_start();
+            t.starts_with("#[test]") || t.starts_with("#[cfg(test)]") || t.starts_with("@Test")
+        })
+    {
+        risks.insert(RiskFlag::Tests);
+    }
+    if category == FileCategory::Config {
+        risks.insert(RiskFlag::Config);
+    }
+    // Outside config files only quoted values count, so parameters such as
+    // `token: &str` are not mistaken for credentials.
+    if changed()
+        .any(|t| (category == FileCategory::Config || t.contains('"')) && redact_secrets(t) != t)
+    {
+        risks.insert(RiskFlag::Secrets);
+    }
+
+    HunkSummary {
+        old_start: raw.old_start,
+        new_start: raw.new_start,
+        additions: raw.added.len(),
+        deletions: raw.removed.len(),
+        symbols,
+        risks,
+    }
+}
+
+fn is_test_symbol(symbol: &Symbol) -> bool {
+    (symbol.kind == SymbolKind::Module && symbol.name == "tests")
+        || (symbol.kind == SymbolKind::Function
+            && (symbol.name.starts_with("test_") || symbol.name.starts_with("Test")))
+}
+
+/// Whether a definition is visible outside its module or package.
+fn is_public(ext: &str, signature: &str, name: &str) -> bool {
+    match ext {
+        "rs" => signature.starts_with("pub ") || signature.starts_with("pub async "),
+        "js" | "jsx" | "ts" | "tsx" => signature.starts_with("export "),
+        "go" => name.starts_with(|c: char| c.is_ascii_uppercase()),
+        "py" | "rb" => !name.starts_with('_'),
+        "java" | "kt" | "scala" | "cs" => signature.contains("public "),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/auth.rs b/src/auth.rs
+index 1111111..2222222 100644
+--- a/src/auth.rs
++++ b/src/auth.rs
+@@ -1,5 +1,6 @@
+-pub fn resolve_auth(token: &str) -> bool {
+-    !token.is_empty()
++pub fn resolve_auth(token: &str) -> Result<bool, AuthError> {
++    let trimmed = token.trim();
++    Ok(!trimmed.is_empty())
+ }
+
+ fn helper() {
+@@ -10,3 +11,3 @@ fn helper() {
+ fn cache() {
+-    let size = 10;
++    let size = 20;
+ }
+diff --git a/config/app.toml b/config/app.toml
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/config/app.toml
+@@ -0,0 +1,2 @@
++[auth]
++api_key = \"sk-ant-REDACTED\"
+diff --git a/old.rs b/tests/new_test.rs
+similarity index 90%
+rename from old.rs
+rename to tests/new_test.rs
+diff --git a/logo.png b/logo.png
+index 4444444..5555555 100644
+Binary files a/logo.png and b/logo.png differ
+";
+
+    const NEW_AUTH: &str = "pub fn resolve_auth(token: &str) -> Result<bool, AuthError> {\n    let trimmed = token.trim();\n    Ok(!trimmed.is_empty())\n}\n\nfn helper() {\n}\n\nfn cache() {\n}\n\nfn cache_size() {\n    let size = 20;\n}\n";
+
+    fn summary() -> DiffSummary {
+        DiffSummary::from_diff(DIFF, |path| {
+            (path == "src/auth.rs").then(|| NEW_AUTH.to_string())
+        })
+    }
+
+    #[test]
+    fn test_parses_change_types_and_stats() {
+        let summary = summary();
+        let changes: Vec<(&str, ChangeType)> = summary
+            .files
+            .iter()
+            .map(|f| (f.path.as_str(), f.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("src/auth.rs", ChangeType::Modified),
+                ("config/app.toml", ChangeType::Added),
+                ("tests/new_test.rs", ChangeType::Renamed),
+                ("logo.png", ChangeType::Binary),
+            ]
+        );
+        assert_eq!(summary.files[2].old_path.as_deref(), Some("old.rs"));
+        assert_eq!(summary.stats.files, 4);
+        assert_eq!(summary.stats.additions, 6);
+        assert_eq!(summary.stats.deletions, 3);
+        assert!(summary.detailed);
+    }
+
+    #[test]
+    fn test_maps_hunks_to_symbols_and_risks() {
+        let summary = summary();
+        let auth = &summary.files[0];
+        assert_eq!(auth.hunks.len(), 2);
+        assert_eq!(
+            auth.hunks[0].symbols,
+            vec![SymbolChange {
+                kind: SymbolKind::Function,
+                name: "resolve_auth".into(),
+                change: SymbolChangeKind::Modified,
+            }]
+        );
+        assert!(auth.hunks[0].risks.contains(&RiskFlag::PublicApi));
+        assert!(auth.hunks[0].risks.contains(&RiskFlag::ErrorHandling));
+        // The second hunk's change sits in `cache_size` in the new file.
+        assert_eq!(auth.hunks[1].symbols[0].name, "cache_size");
+        assert!(auth.hunks[1].risks.is_empty());
+
+        let config = &summary.files[1];
+        assert_eq!(config.category, FileCategory::Config);
+        assert!(config.risks.contains(&RiskFlag::Config));
+        assert!(config.risks.contains(&RiskFlag::Secrets));
+
+        let renamed = &summary.files[2];
+        assert_eq!(renamed.category, FileCategory::Test);
+        assert!(renamed.risks.contains(&RiskFlag::Tests));
+        assert_eq!(summary.stats.risks[&RiskFlag::PublicApi], 1);
+    }
+
+    #[test]
+    fn test_render_mentions_symbols_and_never_secrets() {
+        let text = summary().render();
+        assert!(text.starts_with("Diff summary: 4 files changed, +6 -3"));
+        assert!(text.contains("modified function `resolve_auth`"));
+        assert!(text.contains("R old.rs -> tests/new_test.rs"));
+        assert!(!text.contains("sk-ant-api03"));
+    }
+
+    #[test]
+    fn test_hunk_header_context_without_content() {
+        let summary = DiffSummary::from_diff(DIFF, |_| None);
+        let auth = &summary.files[0];
+        assert_eq!(auth.hunks[1].symbols[0].name, "helper");
+    }
+
+    #[test]
+    fn test_large_diffs_degrade_to_file_level() {
+        let mut diff = String::new();
+        for i in 0..=MAX_DETAILED_FILES {
+            diff.push_str(&format!(
+                "diff --git a/f{i}.rs b/f{i}.rs\n--- a/f{i}.rs\n+++ b/f{i}.rs\n@@ -1 +1 @@\n-pub fn a() {{}}\n+pub fn b() {{}}\n"
+            ));
+        }
+        assert!(DiffSummary::content_paths(&diff).is_empty());
+        let summary = DiffSummary::from_diff(&diff, |_| panic!("content not needed"));
+        assert!(!summary.detailed);
+        assert!(summary.note.as_deref().unwrap().contains("file-level"));
+        assert!(summary.files.iter().all(|f| f.hunks.is_empty()));
+        assert_eq!(summary.stats.additions, MAX_DETAILED_FILES + 1);
+        let text = summary.render();
+        assert!(text.contains("more files"));
+        assert!(text.lines().count() < MAX_RENDERED_FILES * 3);
+    }
+}
@@ -2,8 +2,11 @@
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use rustant_core::canvas::{
+    ChartDataset, ChartImageOptions, ChartSpec, YAxis, render_chart_png, render_chart_svg,
+};
 use rustant_core::error::ToolError;
-use rustant_core::types::{RiskLevel, ToolOutput};
+use rustant_core::types::{Artifact, Attachment, RiskLevel, ToolOutput};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::path::PathBuf;
@@ -725,7 +728,58 @@ impl ExperimentTrackerTool {
             out.push('\n');
         }
 
-        Ok(ToolOutput::text(out))
+        let Some(chart_path) = args.get("chart").and_then(|v| v.as_str()) else {
+            return Ok(ToolOutput::text(out));
+        };
+        let Some(spec) = metrics_chart(&experiments) else {
+            out.push_str("No numeric metrics to chart.\n");
+            return Ok(ToolOutput::text(out));
+        };
+        let failed = |message: String| ToolError::ExecutionFailed {
+            name: "experiment_tracker".into(),
+            message,
+        };
+        let options = ChartImageOptions::default();
+        let (bytes, mime_type) = match std::path::Path::new(chart_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("svg") => (
+                render_chart_svg(&spec, &options)
+                    .map(String::into_bytes)
+                    .map_err(|e| failed(e.to_string()))?,
+                "image/svg+xml",
+            ),
+            Some("png") => (
+                render_chart_png(&spec, &options).map_err(|e| failed(e.to_string()))?,
+                "image/png",
+            ),
+            _ => {
+                return Err(ToolError::InvalidArguments {
+                    name: "experiment_tracker".into(),
+                    reason: "chart: path must end in .svg or .png".into(),
+                });
+            }
+        };
+        let full_path = self.workspace.join(chart_path);
+        if let Some(parent) = full_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| failed(e.to_string()))?;
+        }
+        std::fs::write(&full_path, &bytes)
+            .map_err(|e| failed(format!("Failed to write chart: {}", e)))?;
+        out.push_str(&format!("Metrics chart written to {}\n", chart_path));
+
+        Ok(ToolOutput::text(out)
+            .with_artifact(Artifact::FileCreated {
+                path: PathBuf::from(chart_path),
+            })
+            .with_attachment(Attachment::bytes(
+                mime_type,
+                format!("Metrics chart of {} experiments", experiments.len()),
+                bytes,
+            )))
     }
 
     fn action_summary(&self, args: &Value) -> Result<ToolOutput, ToolError> {
@@ -1008,6 +1062,7 @@ impl Tool for ExperimentTrackerTool {
                     "items": { "type": "string" },
                     "description": "Experiment IDs (for compare_experiments)"
                 },
+                "chart": { "type": "string", "description": "Write a bar chart of numeric metrics to this .svg or .png path (for compare_experiments)" },
                 "tag": { "type": "string", "description": "Filter by tag" }
             },
             "required": ["action"]
@@ -1048,6 +1103,47 @@ impl Tool for ExperimentTrackerTool {
 // Tests
 // ---------------------------------------------------------------------------
 
+/// A grouped bar chart of the experiments' numeric metrics: one bar group
+/// per metric, one dataset per experiment.
+fn metrics_chart(experiments: &[&Experiment]) -> Option<ChartSpec> {
+    let numeric = |exp: &Experiment, name: &str| exp.metrics.get(name).and_then(|v| v.as_f64());
+    let names: std::collections::BTreeSet<String> = experiments
+        .iter()
+        .filter_map(|e| e.metrics.as_object())
+        .flat_map(|m| {
+            m.iter()
+                .filter(|(_, v)| v.is_number())
+                .map(|(k, _)| k.clone())
+        })
+        .collect();
+    if names.is_empty() {
+        return None;
+    }
+    // Metrics an experiment lacks are drawn as zero-height bars.
+    let datasets = experiments
+        .iter()
+        .map(|exp| ChartDataset {
+            label: format!("{} ({})", exp.name, exp.id),
+            data: names
+                .iter()
+                .map(|name| numeric(exp, name).unwrap_or(0.0))
+                .collect(),
+            color: None,
+            y_axis: YAxis::Primary,
+        })
+        .collect();
+    Some(ChartSpec {
+        chart_type: "bar".into(),
+        labels: names.into_iter().collect(),
+        datasets,
+        title: Some("Experiment metrics".into()),
+        x_axis: None,
+        y_axis: None,
+        y2_axis: None,
+        annotations: Vec::new(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1319,6 +1415,41 @@ mod tests {
         assert!(result.content.contains("Comparison of 2 experiments"));
     }
 
+    #[tokio::test]
+    async fn test_compare_experiments_chart() {
+        let (dir, tool) = make_tool();
+        for (id, name, accuracy) in [("e1", "Config A", 0.91), ("e2", "Config B", 0.95)] {
+            tool.execute(json!({"action": "add_experiment", "name": name}))
+                .await
+                .unwrap();
+            tool.execute(json!({"action": "start_experiment", "id": id}))
+                .await
+                .unwrap();
+            tool.execute(json!({
+                "action": "complete_experiment",
+                "id": id,
+                "metrics": {"accuracy": accuracy, "model": "resnet"}
+            }))
+            .await
+            .unwrap();
+        }
+
+        let result = tool
+            .execute(json!({
+                "action": "compare_experiments",
+                "ids": ["e1", "e2"],
+                "chart": "charts/compare.svg"
+            }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("charts/compare.svg"));
+        assert_eq!(result.artifacts.len(), 1);
+        let svg = std::fs::read_to_string(dir.path().join("charts/compare.svg")).unwrap();
+        assert!(svg.contains(">accuracy<"));
+        assert!(svg.contains("Config B (e2)"));
+        assert!(!svg.contains(">model<"));
+    }
+
     #[tokio::test]
     async fn test_summary_empty() {
         let (_dir, tool) = make_tool();
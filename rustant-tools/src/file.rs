@@ -1,10 +1,13 @@
 //! File operation tools: read, list, write, and patch.
 
+use crate::lsp::pre_write::{PreWriteCheck, PreWriteOutcome, pre_write_check};
 use crate::registry::Tool;
 use async_trait::async_trait;
 use rustant_core::error::ToolError;
-use rustant_core::types::{Artifact, RiskLevel, ToolOutput};
+use rustant_core::indexer::WorkspaceIgnore;
+use rustant_core::types::{Artifact, RiskLevel, ToolErrorKind, ToolFailure, ToolOutput};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::{debug, warn};
 
 /// Validate that a path stays inside the workspace.
@@ -30,12 +33,13 @@ fn validate_workspace_path(
 
     // For existing paths, use canonicalize for accurate resolution
     if resolved.exists() {
-        let canonical = resolved
-            .canonicalize()
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: tool_name.into(),
-                message: format!("Path resolution failed: {}", e),
-            })?;
+        let canonical = resolved.canonicalize().map_err(|e| {
+            ToolError::failed(
+                tool_name,
+                format!("Path resolution failed: {}", e),
+                ToolFailure::from_io(&e),
+            )
+        })?;
 
         if !canonical.starts_with(&workspace_canonical) {
             return Err(ToolError::PermissionDenied {
@@ -92,12 +96,13 @@ impl FileReadTool {
         };
 
         // Ensure the path doesn't escape the workspace
-        let canonical = resolved
-            .canonicalize()
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "file_read".into(),
-                message: format!("Path resolution failed: {}", e),
-            })?;
+        let canonical = resolved.canonicalize().map_err(|e| {
+            ToolError::failed(
+                "file_read",
+                format!("Path resolution failed: {}", e),
+                ToolFailure::from_io(&e),
+            )
+        })?;
 
         // Canonicalize workspace too, to handle symlinks (e.g., /var -> /private/var on macOS)
         let workspace_canonical = self
@@ -159,13 +164,13 @@ impl Tool for FileReadTool {
 
         debug!(path = %path.display(), "Reading file");
 
-        let content =
-            tokio::fs::read_to_string(&path)
-                .await
-                .map_err(|e| ToolError::ExecutionFailed {
-                    name: "file_read".into(),
-                    message: format!("Failed to read '{}': {}", path_str, e),
-                })?;
+        let content = tokio::fs::read_to_string(&path).await.map_err(|e| {
+            ToolError::failed(
+                "file_read",
+                format!("Failed to read '{}': {}", path_str, e),
+                ToolFailure::from_io(&e),
+            )
+        })?;
 
         let start_line = args["start_line"].as_u64().map(|n| n as usize);
         let end_line = args["end_line"].as_u64().map(|n| n as usize);
@@ -207,7 +212,7 @@ impl Tool for FileReadTool {
     }
 }
 
-/// List files in a directory, respecting .gitignore patterns.
+/// List files in a directory, respecting .gitignore and .rustantignore patterns.
 pub struct FileListTool {
     workspace: PathBuf,
 }
@@ -225,7 +230,7 @@ impl Tool for FileListTool {
     }
 
     fn description(&self) -> &str {
-        "List files and directories at the given path. Respects .gitignore patterns."
+        "List files and directories at the given path. Respects .gitignore and .rustantignore patterns."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -262,17 +267,21 @@ impl Tool for FileListTool {
         };
 
         if !target_dir.exists() {
-            return Err(ToolError::ExecutionFailed {
-                name: "file_list".into(),
-                message: format!("Directory '{}' does not exist", path_str),
-            });
+            return Err(ToolError::failed(
+                "file_list",
+                format!("Directory '{}' does not exist", path_str),
+                ToolFailure::new(ToolErrorKind::NotFound)
+                    .with_remediation("List the parent directory to find the right path."),
+            ));
         }
 
         if !target_dir.is_dir() {
-            return Err(ToolError::ExecutionFailed {
-                name: "file_list".into(),
-                message: format!("'{}' is not a directory", path_str),
-            });
+            return Err(ToolError::failed(
+                "file_list",
+                format!("'{}' is not a directory", path_str),
+                ToolFailure::new(ToolErrorKind::InvalidArguments)
+                    .with_remediation("Use file_read for files; file_list takes a directory."),
+            ));
         }
 
         debug!(path = %target_dir.display(), recursive, max_depth, "Listing directory");
@@ -281,11 +290,13 @@ impl Tool for FileListTool {
 
         if recursive {
             // Use ignore crate for .gitignore-aware walking
-            let walker = ignore::WalkBuilder::new(&target_dir)
+            let mut builder = ignore::WalkBuilder::new(&target_dir);
+            builder
                 .max_depth(Some(max_depth))
                 .hidden(false)
-                .git_ignore(true)
-                .build();
+                .git_ignore(true);
+            WorkspaceIgnore::load(&self.workspace).apply(&mut builder);
+            let walker = builder.build();
 
             for entry in walker {
                 match entry {
@@ -304,31 +315,28 @@ impl Tool for FileListTool {
                 }
             }
         } else {
-            let mut read_dir =
-                tokio::fs::read_dir(&target_dir)
-                    .await
-                    .map_err(|e| ToolError::ExecutionFailed {
-                        name: "file_list".into(),
-                        message: format!("Failed to read directory '{}': {}", path_str, e),
-                    })?;
-
-            while let Some(entry) =
-                read_dir
-                    .next_entry()
-                    .await
-                    .map_err(|e| ToolError::ExecutionFailed {
-                        name: "file_list".into(),
-                        message: format!("Error reading entry: {}", e),
-                    })?
-            {
-                let file_type =
-                    entry
-                        .file_type()
-                        .await
-                        .map_err(|e| ToolError::ExecutionFailed {
-                            name: "file_list".into(),
-                            message: format!("Error reading file type: {}", e),
-                        })?;
+            let mut read_dir = tokio::fs::read_dir(&target_dir).await.map_err(|e| {
+                ToolError::failed(
+                    "file_list",
+                    format!("Failed to read directory '{}': {}", path_str, e),
+                    ToolFailure::from_io(&e),
+                )
+            })?;
+
+            while let Some(entry) = read_dir.next_entry().await.map_err(|e| {
+                ToolError::failed(
+                    "file_list",
+                    format!("Error reading entry: {}", e),
+                    ToolFailure::from_io(&e),
+                )
+            })? {
+                let file_type = entry.file_type().await.map_err(|e| {
+                    ToolError::failed(
+                        "file_list",
+                        format!("Error reading file type: {}", e),
+                        ToolFailure::from_io(&e),
+                    )
+                })?;
 
                 let name = entry.file_name().to_string_lossy().to_string();
                 let type_indicator = if file_type.is_dir() { "/" } else { "" };
@@ -435,11 +443,11 @@ impl Tool for FileSearchTool {
             // Path points to a specific file — search just that file.
             files_to_search.push(target_path);
         } else {
-            // Walk directory respecting .gitignore.
-            let walker = ignore::WalkBuilder::new(&target_path)
-                .hidden(false)
-                .git_ignore(true)
-                .build();
+            // Walk directory respecting .gitignore and .rustantignore.
+            let mut builder = ignore::WalkBuilder::new(&target_path);
+            builder.hidden(false).git_ignore(true);
+            WorkspaceIgnore::load(&self.workspace).apply(&mut builder);
+            let walker = builder.build();
 
             for entry in walker {
                 let entry = match entry {
@@ -569,11 +577,21 @@ const MAX_WRITE_BYTES: usize = 10 * 1024 * 1024;
 /// Write contents to a file (create or overwrite).
 pub struct FileWriteTool {
     workspace: PathBuf,
+    pre_write: Option<Arc<PreWriteCheck>>,
 }
 
 impl FileWriteTool {
     pub fn new(workspace: PathBuf) -> Self {
-        Self { workspace }
+        Self {
+            workspace,
+            pre_write: pre_write_check(),
+        }
+    }
+
+    /// Check code with `check` before writing it.
+    pub fn with_pre_write_check(mut self, check: Arc<PreWriteCheck>) -> Self {
+        self.pre_write = Some(check);
+        self
     }
 }
 
@@ -635,14 +653,23 @@ impl Tool for FileWriteTool {
         let _ = validate_workspace_path(&self.workspace, path_str, "file_write")?;
         let path = self.workspace.join(path_str);
 
+        let mut note = None;
+        if let Some(check) = &self.pre_write {
+            match check.review(&path, path_str, content).await {
+                PreWriteOutcome::Reject { prompt } => return Ok(ToolOutput::error(prompt)),
+                PreWriteOutcome::Write { note: n } => note = n,
+            }
+        }
+
         // Create parent directories if needed
         if let Some(parent) = path.parent() {
-            tokio::fs::create_dir_all(parent)
-                .await
-                .map_err(|e| ToolError::ExecutionFailed {
-                    name: "file_write".into(),
-                    message: format!("Failed to create directories: {}", e),
-                })?;
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                ToolError::failed(
+                    "file_write",
+                    format!("Failed to create directories: {}", e),
+                    ToolFailure::from_io(&e),
+                )
+            })?;
         }
 
         let existed = path.exists();
@@ -650,12 +677,13 @@ impl Tool for FileWriteTool {
 
         debug!(path = %path.display(), bytes, existed, "Writing file");
 
-        tokio::fs::write(&path, content)
-            .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "file_write".into(),
-                message: format!("Failed to write '{}': {}", path_str, e),
-            })?;
+        tokio::fs::write(&path, content).await.map_err(|e| {
+            ToolError::failed(
+                "file_write",
+                format!("Failed to write '{}': {}", path_str, e),
+                ToolFailure::from_io(&e),
+            )
+        })?;
 
         let action = if existed { "Updated" } else { "Created" };
         let artifact = if existed {
@@ -669,10 +697,12 @@ impl Tool for FileWriteTool {
             }
         };
 
-        Ok(
-            ToolOutput::text(format!("{} '{}' ({} bytes)", action, path_str, bytes))
-                .with_artifact(artifact),
-        )
+        let mut summary = format!("{} '{}' ({} bytes)", action, path_str, bytes);
+        if let Some(note) = note {
+            summary.push('\n');
+            summary.push_str(&note);
+        }
+        Ok(ToolOutput::text(summary).with_artifact(artifact))
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -746,33 +776,37 @@ impl Tool for FilePatchTool {
         let _ = validate_workspace_path(&self.workspace, path_str, "file_patch")?;
         let path = self.workspace.join(path_str);
 
-        let content =
-            tokio::fs::read_to_string(&path)
-                .await
-                .map_err(|e| ToolError::ExecutionFailed {
-                    name: "file_patch".into(),
-                    message: format!("Failed to read '{}': {}", path_str, e),
-                })?;
+        let content = tokio::fs::read_to_string(&path).await.map_err(|e| {
+            ToolError::failed(
+                "file_patch",
+                format!("Failed to read '{}': {}", path_str, e),
+                ToolFailure::from_io(&e),
+            )
+        })?;
 
         if !content.contains(old_text) {
-            return Err(ToolError::ExecutionFailed {
-                name: "file_patch".into(),
-                message: format!(
+            return Err(ToolError::failed(
+                "file_patch",
+                format!(
                     "Could not find the specified text in '{}'. The old_text must match exactly.",
                     path_str
                 ),
-            });
+                ToolFailure::new(ToolErrorKind::Conflict).with_remediation(
+                    "Read the file again and copy old_text exactly, including whitespace.",
+                ),
+            ));
         }
 
         let count = content.matches(old_text).count();
         let new_content = content.replacen(old_text, new_text, 1);
 
-        tokio::fs::write(&path, &new_content)
-            .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "file_patch".into(),
-                message: format!("Failed to write '{}': {}", path_str, e),
-            })?;
+        tokio::fs::write(&path, &new_content).await.map_err(|e| {
+            ToolError::failed(
+                "file_patch",
+                format!("Failed to write '{}': {}", path_str, e),
+                ToolFailure::from_io(&e),
+            )
+        })?;
 
         let mut output = ToolOutput::text(format!(
             "Patched '{}' ({} occurrence{} found, replaced first)",
@@ -1095,6 +1129,44 @@ mod tests {
         assert_eq!(content, "New content!");
     }
 
+    #[tokio::test]
+    async fn test_file_write_returns_new_errors_instead_of_writing() {
+        let dir = setup_workspace();
+        let check = Arc::new(crate::lsp::pre_write::marker_check(
+            3,
+            std::time::Duration::ZERO,
+        ));
+        let tool = FileWriteTool::new(dir.path().to_path_buf()).with_pre_write_check(check);
+
+        let result = tool
+            .execute(serde_json::json!({
+                "path": "src/added.rs",
+                "content": "fn a() {}\nBROKEN b\n"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.metadata.get("is_error"),
+            Some(&serde_json::json!(true))
+        );
+        assert!(
+            result.content.contains("- line 2: BROKEN b"),
+            "{}",
+            result.content
+        );
+        assert!(!dir.path().join("src/added.rs").exists());
+
+        let result = tool
+            .execute(serde_json::json!({
+                "path": "src/added.rs",
+                "content": "fn a() {}\nfn b() {}\n"
+            }))
+            .await
+            .unwrap();
+        assert!(result.content.starts_with("Created"), "{}", result.content);
+        assert!(dir.path().join("src/added.rs").exists());
+    }
+
     #[tokio::test]
     async fn test_file_write_overwrite_existing() {
         let dir = setup_workspace();
@@ -1185,11 +1257,16 @@ mod tests {
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            ToolError::ExecutionFailed { name, message } => {
+            ToolError::Failed {
+                name,
+                message,
+                failure,
+            } => {
                 assert_eq!(name, "file_patch");
                 assert!(message.contains("Could not find"));
+                assert_eq!(failure.kind, ToolErrorKind::Conflict);
             }
-            e => panic!("Expected ExecutionFailed, got: {:?}", e),
+            e => panic!("Expected Failed, got: {:?}", e),
         }
     }
 
@@ -0,0 +1,614 @@
+//! File organizer tool — organize, deduplicate, and clean up files.
+//!
+//! Organizing is always two steps: `organize` builds a stored plan from a
+//! declarative profile and moves nothing; `apply` carries out an approved
+//! plan while journaling every move, and `undo` reverses it.
+
+pub mod plan;
+pub mod profile;
+
+use async_trait::async_trait;
+use rustant_core::error::ToolError;
+use rustant_core::types::{RiskLevel, ToolOutput};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use walkdir::WalkDir;
+
+use crate::registry::Tool;
+use plan::{ORGANIZER_DIR, OrganizePlan, OrganizerError, PlanState};
+use profile::{BY_EXTENSION, ConflictPolicy, OrganizerProfile};
+
+/// Plan entries listed in tool output; the stored plan has all of them.
+const PLAN_DISPLAY_LIMIT: usize = 500;
+
+pub struct FileOrganizerTool {
+    workspace: PathBuf,
+}
+
+impl FileOrganizerTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+
+    fn hash_file(path: &std::path::Path) -> Option<String> {
+        let data = std::fs::read(path).ok()?;
+        let mut hasher = Sha256::new();
+        hasher.update(&data);
+        Some(format!("{:x}", hasher.finalize()))
+    }
+
+    fn profiles_dir(&self) -> PathBuf {
+        self.workspace.join(ORGANIZER_DIR).join("profiles")
+    }
+
+    /// Saved profiles plus the built-in one, sorted by name.
+    fn list_profiles(&self) -> Vec<OrganizerProfile> {
+        let mut profiles = vec![OrganizerProfile::by_extension()];
+        if let Ok(dir) = std::fs::read_dir(self.profiles_dir()) {
+            profiles.extend(
+                dir.filter_map(|e| e.ok())
+                    .filter(|e| e.path().extension().is_some_and(|x| x == "json"))
+                    .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+                    .filter_map(|data| serde_json::from_str::<OrganizerProfile>(&data).ok())
+                    .filter(|p| p.name != BY_EXTENSION),
+            );
+        }
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        profiles
+    }
+
+    /// The profile for a call: an inline `profile_definition`, else a named
+    /// profile, else the built-in `by_extension`.
+    fn resolve_profile(&self, args: &Value) -> Result<OrganizerProfile, ToolError> {
+        if let Some(definition) = args.get("profile_definition") {
+            return Self::parse_profile(definition);
+        }
+        let name = args
+            .get("profile")
+            .and_then(|v| v.as_str())
+            .unwrap_or(BY_EXTENSION);
+        self.list_profiles()
+            .into_iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| ToolError::InvalidArguments {
+                name: "file_organizer".to_string(),
+                reason: format!(
+                    "unknown profile '{}'. Available: {}",
+                    name,
+                    self.list_profiles()
+                        .iter()
+                        .map(|p| p.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            })
+    }
+
+    fn parse_profile(definition: &Value) -> Result<OrganizerProfile, ToolError> {
+        let invalid = |reason: String| ToolError::InvalidArguments {
+            name: "file_organizer".to_string(),
+            reason,
+        };
+        let profile: OrganizerProfile = serde_json::from_value(definition.clone())
+            .map_err(|e| invalid(format!("invalid profile_definition: {}", e)))?;
+        profile
+            .validate()
+            .map_err(|e| invalid(format!("invalid profile_definition: {}", e)))?;
+        Ok(profile)
+    }
+
+    fn conflict_arg(args: &Value) -> Result<Option<ConflictPolicy>, ToolError> {
+        match args.get("conflict").and_then(|v| v.as_str()) {
+            None => Ok(None),
+            Some(s) => {
+                ConflictPolicy::parse(s)
+                    .map(Some)
+                    .ok_or_else(|| ToolError::InvalidArguments {
+                        name: "file_organizer".to_string(),
+                        reason: format!("conflict must be skip, rename or overwrite, not '{}'", s),
+                    })
+            }
+        }
+    }
+
+    fn plan_id_arg(args: &Value) -> Result<&str, ToolError> {
+        args.get("plan_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidArguments {
+                name: "file_organizer".to_string(),
+                reason: "plan_id is required; run action 'organize' first to create a plan"
+                    .to_string(),
+            })
+    }
+
+    fn failed(e: OrganizerError) -> ToolError {
+        ToolError::ExecutionFailed {
+            name: "file_organizer".to_string(),
+            message: e.to_string(),
+        }
+    }
+
+    fn organize(&self, target: &std::path::Path, args: &Value) -> Result<ToolOutput, ToolError> {
+        let profile = self.resolve_profile(args)?;
+        let conflict = Self::conflict_arg(args)?.unwrap_or(profile.conflict);
+        let plan =
+            plan::build_plan(&self.workspace, target, &profile, conflict).map_err(Self::failed)?;
+
+        let mut output = plan.render(PLAN_DISPLAY_LIMIT);
+        if plan.count(plan::EntryAction::Move) + plan.count(plan::EntryAction::Tag) == 0 {
+            output.push_str("\nNothing to do.");
+        } else {
+            output.push_str(&format!(
+                "\nDry run — nothing has been moved. After review, call file_organizer with \
+                 action \"apply\" and plan_id \"{}\"{}.",
+                plan.id,
+                if plan.overwrites() > 0 {
+                    " and conflict \"overwrite\" (overwrites need elevated approval)"
+                } else {
+                    ""
+                }
+            ));
+        }
+        let mut result = ToolOutput::text(output);
+        result
+            .metadata
+            .insert("plan_id".into(), Value::String(plan.id.clone()));
+        result.metadata.insert(
+            "plan".into(),
+            serde_json::to_value(&plan).unwrap_or(Value::Null),
+        );
+        Ok(result)
+    }
+
+    fn apply(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let id = Self::plan_id_arg(args)?;
+        let plan = plan::load_plan(&self.workspace, id).map_err(Self::failed)?;
+        if plan.overwrites() > 0 && Self::conflict_arg(args)? != Some(ConflictPolicy::Overwrite) {
+            return Ok(ToolOutput::error(format!(
+                "Plan {} overwrites {} existing files. Call apply again with conflict \
+                 \"overwrite\" to confirm; that call needs elevated approval.",
+                plan.id,
+                plan.overwrites()
+            )));
+        }
+        let (plan, report) = plan::apply_plan(&self.workspace, id).map_err(Self::failed)?;
+        Ok(ToolOutput::text(format!(
+            "{}\nUndo with action \"undo\" and plan_id \"{}\".",
+            report.render("moved or tagged", &plan),
+            plan.id
+        )))
+    }
+
+    fn undo(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let id = args.get("plan_id").and_then(|v| v.as_str());
+        let (plan, report) = plan::undo_plan(&self.workspace, id).map_err(Self::failed)?;
+        Ok(ToolOutput::text(report.render("restored", &plan)))
+    }
+
+    fn plans(&self) -> ToolOutput {
+        let plans = plan::list_plans(&self.workspace);
+        if plans.is_empty() {
+            return ToolOutput::text(
+                "No organization plans yet. Create one with action 'organize'.",
+            );
+        }
+        let mut output = String::from("Organization plans (newest first):\n");
+        for plan in plans.iter().take(20) {
+            output.push_str(&format!(
+                "  {}  {:<17}  {}/ with '{}': {}\n",
+                plan.id,
+                plan.state,
+                plan_root(plan),
+                plan.profile,
+                plan.summary()
+            ));
+            if plan.state == PlanState::Applying {
+                output
+                    .push_str("      interrupted — apply again to resume, or undo to roll back\n");
+            }
+        }
+        ToolOutput::text(output)
+    }
+
+    fn profiles(&self) -> ToolOutput {
+        let mut output = String::from("Organization profiles:\n");
+        for profile in self.list_profiles() {
+            output.push_str(&format!(
+                "  {} ({} rules, conflicts: {}{}){}\n",
+                profile.name,
+                profile.rules.len(),
+                profile.conflict.as_str(),
+                if profile.recursive { ", recursive" } else { "" },
+                profile
+                    .description
+                    .as_deref()
+                    .map(|d| format!(" — {}", d))
+                    .unwrap_or_default()
+            ));
+        }
+        output.push_str(&format!(
+            "\nSaved profiles live in {}/profiles/.",
+            ORGANIZER_DIR
+        ));
+        ToolOutput::text(output)
+    }
+
+    fn save_profile(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let definition =
+            args.get("profile_definition")
+                .ok_or_else(|| ToolError::InvalidArguments {
+                    name: "file_organizer".to_string(),
+                    reason: "profile_definition is required".to_string(),
+                })?;
+        let profile = Self::parse_profile(definition)?;
+        if profile.name == BY_EXTENSION {
+            return Ok(ToolOutput::error(format!(
+                "'{}' is built in; choose another name.",
+                BY_EXTENSION
+            )));
+        }
+        let dir = self.profiles_dir();
+        let write = || -> std::io::Result<()> {
+            std::fs::create_dir_all(&dir)?;
+            let json = serde_json::to_string_pretty(&profile)?;
+            std::fs::write(dir.join(format!("{}.json", profile.name)), json)
+        };
+        write().map_err(|e| ToolError::ExecutionFailed {
+            name: "file_organizer".to_string(),
+            message: format!("Failed to save profile: {}", e),
+        })?;
+        Ok(ToolOutput::text(format!(
+            "Saved profile '{}' with {} rules.",
+            profile.name,
+            profile.rules.len()
+        )))
+    }
+}
+
+fn plan_root(plan: &OrganizePlan) -> String {
+    let root = plan.root.display().to_string();
+    if root.is_empty() { ".".into() } else { root }
+}
+
+#[async_trait]
+impl Tool for FileOrganizerTool {
+    fn name(&self) -> &str {
+        "file_organizer"
+    }
+    fn description(&self) -> &str {
+        "Organize, deduplicate, and clean up files. Actions: organize (dry-run plan from a \
+         rule profile; moves nothing), apply (carry out a plan by plan_id), undo (restore the \
+         layout before a plan), plans, profiles, save_profile, dedup, cleanup, preview."
+    }
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["organize", "apply", "undo", "plans", "profiles", "save_profile", "dedup", "cleanup", "preview"],
+                    "description": "Action to perform"
+                },
+                "path": { "type": "string", "description": "Target directory path" },
+                "profile": { "type": "string", "description": "Saved profile name for organize (default: by_extension)" },
+                "profile_definition": {
+                    "type": "object",
+                    "description": "Inline profile for organize, or the profile to store for save_profile: {name, description?, conflict?, recursive?, rules: [{name?, match: {glob?, extensions?, min_age_days?, max_age_days?, min_size?, max_size?, content_types?}, action: {type: move, to, rename?} | {type: rename, pattern} | {type: tag, tags} | {type: skip}}]}. Templates may use {name} {stem} {ext} {year} {month} {day} {date} {type}."
+                },
+                "conflict": {
+                    "type": "string",
+                    "enum": ["skip", "rename", "overwrite"],
+                    "description": "Conflict policy for organize; for apply, 'overwrite' confirms a plan that replaces files"
+                },
+                "plan_id": { "type": "string", "description": "Plan to apply or undo (undo defaults to the latest applied plan)" },
+                "pattern": { "type": "string", "description": "File glob pattern for cleanup (e.g., '*.tmp')" },
+                "dry_run": { "type": "boolean", "description": "Preview changes without applying (default: true)", "default": true }
+            },
+            "required": ["action"]
+        })
+    }
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Write
+    }
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(120)
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolOutput, ToolError> {
+        let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        let target = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(|p| self.workspace.join(p))
+            .unwrap_or_else(|| self.workspace.clone());
+        let dry_run = args
+            .get("dry_run")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        // Validate target is within workspace
+        let canonical = target.canonicalize().unwrap_or_else(|_| target.clone());
+        if !canonical.starts_with(&self.workspace) {
+            return Ok(ToolOutput::text("Error: Path must be within workspace."));
+        }
+
+        match action {
+            "organize" => self.organize(&canonical, &args),
+            "apply" => self.apply(&args),
+            "undo" => self.undo(&args),
+            "plans" => Ok(self.plans()),
+            "profiles" => Ok(self.profiles()),
+            "save_profile" => self.save_profile(&args),
+            "dedup" => {
+                let mut hashes: HashMap<String, Vec<PathBuf>> = HashMap::new();
+                let mut file_count = 0;
+                for entry in WalkDir::new(&target)
+                    .max_depth(3)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                {
+                    if entry.file_type().is_file() {
+                        file_count += 1;
+                        if let Some(hash) = Self::hash_file(entry.path()) {
+                            hashes
+                                .entry(hash)
+                                .or_default()
+                                .push(entry.path().to_path_buf());
+                        }
+                    }
+                }
+                let dups: Vec<_> = hashes.values().filter(|v| v.len() > 1).collect();
+                if dups.is_empty() {
+                    return Ok(ToolOutput::text(format!(
+                        "No duplicates found among {} files.",
+                        file_count
+                    )));
+                }
+                let mut output = format!(
+                    "Found {} duplicate groups among {} files:\n",
+                    dups.len(),
+                    file_count
+                );
+                for (i, group) in dups.iter().enumerate().take(20) {
+                    output.push_str(&format!("  Group {}:\n", i + 1));
+                    for path in *group {
+                        let rel = path.strip_prefix(&self.workspace).unwrap_or(path);
+                        output.push_str(&format!("    {}\n", rel.display()));
+                    }
+                }
+                if dry_run {
+                    output.push_str("\n(Dry run — no files deleted.)");
+                }
+                Ok(ToolOutput::text(output))
+            }
+            "cleanup" => {
+                let pattern = args
+                    .get("pattern")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("*.tmp");
+                let glob = globset::GlobBuilder::new(pattern)
+                    .build()
+                    .map(|g| g.compile_matcher())
+                    .ok();
+                let mut matches = Vec::new();
+                for entry in WalkDir::new(&target)
+                    .max_depth(3)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                {
+                    if entry.file_type().is_file() {
+                        let name = entry.file_name().to_string_lossy();
+                        if let Some(ref glob) = glob
+                            && glob.is_match(name.as_ref())
+                        {
+                            matches.push(entry.path().to_path_buf());
+                        }
+                    }
+                }
+                if matches.is_empty() {
+                    return Ok(ToolOutput::text(format!(
+                        "No files matching '{}'.",
+                        pattern
+                    )));
+                }
+                let mut output = format!("Found {} files matching '{}':\n", matches.len(), pattern);
+                for path in &matches {
+                    let rel = path.strip_prefix(&self.workspace).unwrap_or(path);
+                    output.push_str(&format!("  {}\n", rel.display()));
+                }
+                if dry_run {
+                    output.push_str("\n(Dry run — no files deleted.)");
+                } else {
+                    let mut deleted = 0;
+                    for path in &matches {
+                        if std::fs::remove_file(path).is_ok() {
+                            deleted += 1;
+                        }
+                    }
+                    output.push_str(&format!("\nDeleted {} files.", deleted));
+                }
+                Ok(ToolOutput::text(output))
+            }
+            "preview" => {
+                let mut total_files = 0;
+                let mut total_size: u64 = 0;
+                let mut by_ext: HashMap<String, (usize, u64)> = HashMap::new();
+                for entry in WalkDir::new(&target)
+                    .max_depth(3)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                {
+                    if entry.file_type().is_file() {
+                        total_files += 1;
+                        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                        total_size += size;
+                        let ext = entry
+                            .path()
+                            .extension()
+                            .and_then(|e| e.to_str())
+                            .unwrap_or("none")
+                            .to_lowercase();
+                        let entry = by_ext.entry(ext).or_insert((0, 0));
+                        entry.0 += 1;
+                        entry.1 += size;
+                    }
+                }
+                let mut output = format!(
+                    "Directory preview: {} files, {:.1} MB\n",
+                    total_files,
+                    total_size as f64 / 1_048_576.0
+                );
+                let mut sorted: Vec<_> = by_ext.iter().collect();
+                sorted.sort_by_key(|b| std::cmp::Reverse(b.1.1));
+                for (ext, (count, size)) in sorted.iter().take(15) {
+                    output.push_str(&format!(
+                        "  .{:<10} {:>5} files  {:>8.1} KB\n",
+                        ext,
+                        count,
+                        *size as f64 / 1024.0
+                    ));
+                }
+                Ok(ToolOutput::text(output))
+            }
+            _ => Ok(ToolOutput::text(format!(
+                "Unknown action: {}. Use: organize, apply, undo, plans, profiles, save_profile, \
+                 dedup, cleanup, preview",
+                action
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_file_organizer_preview() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        std::fs::write(workspace.join("test.txt"), "hello").unwrap();
+        std::fs::write(workspace.join("data.csv"), "a,b").unwrap();
+
+        let tool = FileOrganizerTool::new(workspace);
+        let result = tool.execute(json!({"action": "preview"})).await.unwrap();
+        assert!(result.content.contains("files"));
+    }
+
+    #[tokio::test]
+    async fn test_file_organizer_dedup() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        std::fs::write(workspace.join("a.txt"), "same content").unwrap();
+        std::fs::write(workspace.join("b.txt"), "same content").unwrap();
+        std::fs::write(workspace.join("c.txt"), "different").unwrap();
+
+        let tool = FileOrganizerTool::new(workspace);
+        let result = tool
+            .execute(json!({"action": "dedup", "dry_run": true}))
+            .await
+            .unwrap();
+        assert!(result.content.contains("duplicate"));
+    }
+
+    #[tokio::test]
+    async fn test_file_organizer_no_dupes() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        std::fs::write(workspace.join("a.txt"), "unique a").unwrap();
+        std::fs::write(workspace.join("b.txt"), "unique b").unwrap();
+
+        let tool = FileOrganizerTool::new(workspace);
+        let result = tool.execute(json!({"action": "dedup"})).await.unwrap();
+        assert!(result.content.contains("No duplicates"));
+    }
+
+    #[tokio::test]
+    async fn test_file_organizer_plan_apply_undo() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        std::fs::write(workspace.join("notes.txt"), "n").unwrap();
+        std::fs::write(workspace.join("photo.png"), [0x89, b'P', b'N', b'G']).unwrap();
+
+        let tool = FileOrganizerTool::new(workspace.clone());
+        let definition = json!({
+            "name": "media",
+            "rules": [{
+                "name": "images",
+                "match": {"content_types": ["image/*"]},
+                "action": {"type": "move", "to": "Pictures/{year}"}
+            }]
+        });
+        let saved = tool
+            .execute(json!({"action": "save_profile", "profile_definition": definition}))
+            .await
+            .unwrap();
+        assert!(saved.content.contains("Saved profile 'media'"));
+
+        let planned = tool
+            .execute(json!({"action": "organize", "profile": "media", "dry_run": false}))
+            .await
+            .unwrap();
+        assert!(planned.content.contains("MOVE  photo.png -> Pictures/"));
+        assert!(workspace.join("photo.png").exists());
+        let plan_id = planned.metadata["plan_id"].as_str().unwrap().to_string();
+
+        let applied = tool
+            .execute(json!({"action": "apply", "plan_id": plan_id}))
+            .await
+            .unwrap();
+        assert!(applied.content.contains("1 files moved or tagged"));
+        assert!(!workspace.join("photo.png").exists());
+        assert!(workspace.join("notes.txt").exists());
+
+        tool.execute(json!({"action": "undo"})).await.unwrap();
+        assert!(workspace.join("photo.png").exists());
+        assert!(!workspace.join("Pictures").exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_organizer_overwrite_needs_confirmation() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(workspace.join("txt")).unwrap();
+        std::fs::write(workspace.join("txt/a.txt"), "old").unwrap();
+        std::fs::write(workspace.join("a.txt"), "new").unwrap();
+
+        let tool = FileOrganizerTool::new(workspace.clone());
+        let planned = tool
+            .execute(json!({"action": "organize", "conflict": "overwrite"}))
+            .await
+            .unwrap();
+        assert!(planned.content.contains("CONFLICT"));
+        let plan_id = planned.metadata["plan_id"].as_str().unwrap().to_string();
+
+        let refused = tool
+            .execute(json!({"action": "apply", "plan_id": plan_id}))
+            .await
+            .unwrap();
+        assert!(refused.content.contains("overwrites 1 existing files"));
+        assert_eq!(
+            std::fs::read_to_string(workspace.join("txt/a.txt")).unwrap(),
+            "old"
+        );
+
+        tool.execute(json!({"action": "apply", "plan_id": plan_id, "conflict": "overwrite"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(workspace.join("txt/a.txt")).unwrap(),
+            "new"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_organizer_schema() {
+        let dir = TempDir::new().unwrap();
+        let tool = FileOrganizerTool::new(dir.path().to_path_buf());
+        assert_eq!(tool.name(), "file_organizer");
+    }
+}
@@ -0,0 +1,1126 @@
+//! Organization plans, the move journal, and undo.
+//!
+//! Nothing moves without a stored plan. `build_plan` records every file a
+//! profile touches and where it would go; `apply_plan` performs it while
+//! appending each step to a journal, so an interrupted run can be resumed and
+//! a finished one reversed with `undo_plan`. All paths stored in plans and
+//! journals are relative to the workspace.
+
+use super::profile::{
+    ConflictPolicy, FileFacts, OrganizerProfile, RuleAction, expand_name, expand_template,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// Workspace-relative directory holding profiles, plans, journals and backups.
+pub const ORGANIZER_DIR: &str = ".rustant/organizer";
+
+// ---------------------------------------------------------------------------
+// Error type
+// ---------------------------------------------------------------------------
+
+/// Errors from planning, applying or undoing an organization.
+#[derive(Debug, thiserror::Error)]
+pub enum OrganizerError {
+    #[error("No organization plan with id '{0}'")]
+    PlanNotFound(String),
+
+    #[error("No applied organization plan to undo")]
+    NothingToUndo,
+
+    #[error("Plan '{id}' is {state} and cannot be {action}")]
+    InvalidState {
+        id: String,
+        state: String,
+        action: &'static str,
+    },
+
+    #[error("{0}")]
+    Invalid(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+// ---------------------------------------------------------------------------
+// Plans
+// ---------------------------------------------------------------------------
+
+/// Lifecycle of a stored plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanState {
+    /// Built and waiting for approval; nothing has moved.
+    Planned,
+    /// Apply started but did not finish; apply again to resume.
+    Applying,
+    Applied,
+    Undone,
+}
+
+impl std::fmt::Display for PlanState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Self::Planned => "planned",
+            Self::Applying => "partially applied",
+            Self::Applied => "applied",
+            Self::Undone => "undone",
+        })
+    }
+}
+
+/// What happens to one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryAction {
+    Move,
+    Tag,
+    Skip,
+}
+
+/// One file in a plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanEntry {
+    pub source: PathBuf,
+    /// Where the file goes, or would have gone for skipped conflicts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<PathBuf>,
+    pub action: EntryAction,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// Description of a destination conflict and how the policy resolved it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<String>,
+    /// The move replaces an existing file.
+    #[serde(default)]
+    pub overwrite: bool,
+    /// Why the file is skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub size: u64,
+    pub modified: DateTime<Utc>,
+}
+
+/// A stored organization plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizePlan {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub root: PathBuf,
+    pub profile: String,
+    pub conflict: ConflictPolicy,
+    pub state: PlanState,
+    pub entries: Vec<PlanEntry>,
+    /// Files no rule matched, or already where their rule puts them.
+    #[serde(default)]
+    pub unmatched: usize,
+}
+
+impl OrganizePlan {
+    pub fn count(&self, action: EntryAction) -> usize {
+        self.entries.iter().filter(|e| e.action == action).count()
+    }
+
+    pub fn conflicts(&self) -> usize {
+        self.entries.iter().filter(|e| e.conflict.is_some()).count()
+    }
+
+    pub fn overwrites(&self) -> usize {
+        self.entries.iter().filter(|e| e.overwrite).count()
+    }
+
+    /// One-line summary of what the plan does.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} moves, {} tags, {} skipped, {} conflicts{}",
+            self.count(EntryAction::Move),
+            self.count(EntryAction::Tag),
+            self.count(EntryAction::Skip),
+            self.conflicts(),
+            match self.overwrites() {
+                0 => String::new(),
+                n => format!(" ({} overwrite existing files)", n),
+            }
+        )
+    }
+
+    /// The plan as text, listing at most `limit` entries.
+    pub fn render(&self, limit: usize) -> String {
+        let mut out = format!(
+            "Organization plan {} for {}/ (profile '{}', conflicts: {})\n{}\n\n",
+            self.id,
+            display(&self.root),
+            self.profile,
+            self.conflict.as_str(),
+            self.summary()
+        );
+        for entry in self.entries.iter().take(limit) {
+            let line = match entry.action {
+                EntryAction::Move => format!(
+                    "  MOVE  {} -> {}",
+                    display(&entry.source),
+                    entry
+                        .destination
+                        .as_deref()
+                        .map(display)
+                        .unwrap_or_default()
+                ),
+                EntryAction::Tag => format!(
+                    "  TAG   {} [{}]",
+                    display(&entry.source),
+                    entry.tags.join(", ")
+                ),
+                EntryAction::Skip => match &entry.destination {
+                    Some(dest) => {
+                        format!("  SKIP  {} -> {}", display(&entry.source), display(dest))
+                    }
+                    None => format!("  SKIP  {}", display(&entry.source)),
+                },
+            };
+            out.push_str(&line);
+            if let Some(conflict) = &entry.conflict {
+                out.push_str(&format!("  ⚠ CONFLICT: {}", conflict));
+            } else if let Some(note) = &entry.note {
+                out.push_str(&format!("  ({})", note));
+            }
+            if let Some(rule) = &entry.rule {
+                out.push_str(&format!("  [{}]", rule));
+            }
+            out.push('\n');
+        }
+        if self.entries.len() > limit {
+            out.push_str(&format!(
+                "  ... {} more entries in {}\n",
+                self.entries.len() - limit,
+                display(&plan_path(Path::new(""), &self.id))
+            ));
+        }
+        if self.unmatched > 0 {
+            out.push_str(&format!(
+                "  ({} files left in place: no rule matched or already organized)\n",
+                self.unmatched
+            ));
+        }
+        out
+    }
+}
+
+fn display(path: &Path) -> String {
+    let s = path.display().to_string();
+    if s.is_empty() { ".".into() } else { s }
+}
+
+fn organizer_dir(workspace: &Path) -> PathBuf {
+    workspace.join(ORGANIZER_DIR)
+}
+
+fn plan_path(workspace: &Path, id: &str) -> PathBuf {
+    organizer_dir(workspace)
+        .join("plans")
+        .join(format!("{}.json", id))
+}
+
+fn journal_path(workspace: &Path, id: &str) -> PathBuf {
+    organizer_dir(workspace)
+        .join("journal")
+        .join(format!("{}.jsonl", id))
+}
+
+fn backup_dir(workspace: &Path, id: &str) -> PathBuf {
+    organizer_dir(workspace).join("backups").join(id)
+}
+
+fn tags_path(workspace: &Path) -> PathBuf {
+    organizer_dir(workspace).join("tags.json")
+}
+
+fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<(), OrganizerError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(value)?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+pub fn save_plan(workspace: &Path, plan: &OrganizePlan) -> Result<(), OrganizerError> {
+    write_json_atomic(&plan_path(workspace, &plan.id), plan)
+}
+
+pub fn load_plan(workspace: &Path, id: &str) -> Result<OrganizePlan, OrganizerError> {
+    if id.is_empty() || id.contains(['/', '\\', '.']) {
+        return Err(OrganizerError::PlanNotFound(id.to_string()));
+    }
+    let data = match std::fs::read_to_string(plan_path(workspace, id)) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(OrganizerError::PlanNotFound(id.to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    Ok(serde_json::from_str(&data)?)
+}
+
+/// All stored plans, newest first.
+pub fn list_plans(workspace: &Path) -> Vec<OrganizePlan> {
+    let Ok(dir) = std::fs::read_dir(organizer_dir(workspace).join("plans")) else {
+        return Vec::new();
+    };
+    let mut plans: Vec<OrganizePlan> = dir
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|x| x == "json"))
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .filter_map(|data| serde_json::from_str(&data).ok())
+        .collect();
+    plans.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+    plans
+}
+
+/// Build and store a plan for organizing `root` (inside `workspace`) with
+/// `profile`. Nothing on disk besides the plan file changes.
+pub fn build_plan(
+    workspace: &Path,
+    root: &Path,
+    profile: &OrganizerProfile,
+    conflict: ConflictPolicy,
+) -> Result<OrganizePlan, OrganizerError> {
+    profile.validate().map_err(OrganizerError::Invalid)?;
+    let now = SystemTime::now();
+    let relative = |p: &Path| p.strip_prefix(workspace).unwrap_or(p).to_path_buf();
+    let internal = organizer_dir(workspace)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    let mut entries = Vec::new();
+    let mut unmatched = 0;
+    let mut claimed: HashSet<PathBuf> = HashSet::new();
+
+    let walker = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(if profile.recursive { usize::MAX } else { 1 })
+        .follow_links(false)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !e.path().starts_with(&internal));
+    for entry in walker.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let Ok(metadata) = std::fs::symlink_metadata(path) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            continue;
+        }
+        let modified: DateTime<Utc> = metadata.modified().unwrap_or(now).into();
+        if metadata.file_type().is_symlink() {
+            entries.push(PlanEntry {
+                source: relative(path),
+                destination: None,
+                action: EntryAction::Skip,
+                tags: Vec::new(),
+                rule: None,
+                conflict: None,
+                overwrite: false,
+                note: Some("symlink, not followed".into()),
+                size: 0,
+                modified,
+            });
+            continue;
+        }
+
+        let facts = FileFacts::read(root, path, &metadata);
+        let Some((index, rule)) = profile.rule_for(&facts, now) else {
+            unmatched += 1;
+            continue;
+        };
+        let mut entry = PlanEntry {
+            source: relative(path),
+            destination: None,
+            action: EntryAction::Skip,
+            tags: Vec::new(),
+            rule: Some(rule.label(index)),
+            conflict: None,
+            overwrite: false,
+            note: None,
+            size: metadata.len(),
+            modified,
+        };
+        let target = match &rule.action {
+            RuleAction::Skip => {
+                entry.note = Some("skipped by rule".into());
+                entries.push(entry);
+                continue;
+            }
+            RuleAction::Tag { tags } => {
+                entry.action = EntryAction::Tag;
+                entry.tags = tags.clone();
+                entries.push(entry);
+                continue;
+            }
+            RuleAction::Move { to, rename } => {
+                let dir = root.join(expand_template(to, &facts));
+                let name = match rename {
+                    Some(template) => expand_name(template, &facts),
+                    None => facts.file_name(),
+                };
+                dir.join(name)
+            }
+            RuleAction::Rename { pattern } => path
+                .parent()
+                .unwrap_or(root)
+                .join(expand_name(pattern, &facts)),
+        };
+        if target == path {
+            unmatched += 1;
+            continue;
+        }
+
+        entry.action = EntryAction::Move;
+        let exists = std::fs::symlink_metadata(&target).is_ok();
+        if exists || claimed.contains(&target) {
+            let reason = if exists {
+                format!("{} already exists", display(&relative(&target)))
+            } else {
+                format!(
+                    "another file is planned for {}",
+                    display(&relative(&target))
+                )
+            };
+            match conflict {
+                ConflictPolicy::Rename => {
+                    let free = free_name(&target, &claimed);
+                    entry.conflict = Some(format!(
+                        "{}; renamed to {}",
+                        reason,
+                        free.file_name().unwrap_or_default().to_string_lossy()
+                    ));
+                    claimed.insert(free.clone());
+                    entry.destination = Some(relative(&free));
+                }
+                ConflictPolicy::Overwrite if exists && !claimed.contains(&target) => {
+                    entry.conflict = Some(format!("{}; will be overwritten", reason));
+                    entry.overwrite = true;
+                    claimed.insert(target.clone());
+                    entry.destination = Some(relative(&target));
+                }
+                _ => {
+                    entry.action = EntryAction::Skip;
+                    entry.conflict = Some(format!("{}; skipped", reason));
+                    entry.destination = Some(relative(&target));
+                }
+            }
+        } else {
+            claimed.insert(target.clone());
+            entry.destination = Some(relative(&target));
+        }
+        entries.push(entry);
+    }
+
+    let created_at = Utc::now();
+    let uuid = uuid::Uuid::new_v4().simple().to_string();
+    let plan = OrganizePlan {
+        id: format!("{}-{}", created_at.format("%Y%m%d-%H%M%S"), &uuid[..8]),
+        created_at,
+        root: relative(root),
+        profile: profile.name.clone(),
+        conflict,
+        state: PlanState::Planned,
+        entries,
+        unmatched,
+    };
+    save_plan(workspace, &plan)?;
+    Ok(plan)
+}
+
+/// `report.pdf` → `report-1.pdf`, `report-2.pdf`, ... until a name is free.
+fn free_name(target: &Path, claimed: &HashSet<PathBuf>) -> PathBuf {
+    let stem = target
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = target
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|n| target.with_file_name(format!("{}-{}{}", stem, n, ext)))
+        .find(|candidate| {
+            !claimed.contains(candidate) && std::fs::symlink_metadata(candidate).is_err()
+        })
+        .expect("an unused name exists")
+}
+
+// ---------------------------------------------------------------------------
+// Journal
+// ---------------------------------------------------------------------------
+
+/// One journal line. Moves are written as `Started` before touching the disk
+/// and `Moved` after, so a crash in between can be recognised and repaired.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalRecord {
+    Started {
+        index: usize,
+        from: PathBuf,
+        to: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        backup: Option<PathBuf>,
+        /// Directories created for the destination, outermost first.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        created_dirs: Vec<PathBuf>,
+    },
+    Moved {
+        index: usize,
+    },
+    Tagged {
+        index: usize,
+        added: Vec<String>,
+    },
+    Skipped {
+        index: usize,
+        reason: String,
+    },
+    Undone {
+        index: usize,
+    },
+}
+
+impl JournalRecord {
+    fn index(&self) -> usize {
+        match self {
+            Self::Started { index, .. }
+            | Self::Moved { index }
+            | Self::Tagged { index, .. }
+            | Self::Skipped { index, .. }
+            | Self::Undone { index } => *index,
+        }
+    }
+}
+
+struct Journal {
+    path: PathBuf,
+    records: Vec<JournalRecord>,
+}
+
+impl Journal {
+    fn open(workspace: &Path, id: &str) -> Result<Self, OrganizerError> {
+        let path = journal_path(workspace, id);
+        let records = match std::fs::read_to_string(&path) {
+            // A torn final line from a crash is ignored.
+            Ok(data) => data
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, records })
+    }
+
+    fn append(&mut self, record: JournalRecord) -> Result<(), OrganizerError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        file.sync_data()?;
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// The last record for each entry index.
+    fn latest(&self) -> HashMap<usize, &JournalRecord> {
+        self.records.iter().map(|r| (r.index(), r)).collect()
+    }
+
+    fn started(&self, index: usize) -> Option<&JournalRecord> {
+        self.records
+            .iter()
+            .rev()
+            .find(|r| r.index() == index && matches!(r, JournalRecord::Started { .. }))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tags
+// ---------------------------------------------------------------------------
+
+/// Tags recorded by `tag` rules, keyed by workspace-relative path.
+type TagIndex = BTreeMap<String, BTreeSet<String>>;
+
+pub fn load_tags(workspace: &Path) -> TagIndex {
+    std::fs::read_to_string(tags_path(workspace))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_tags(workspace: &Path, tags: &TagIndex) -> Result<(), OrganizerError> {
+    write_json_atomic(&tags_path(workspace), tags)
+}
+
+fn tag_key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Keep a file's tags attached when it moves.
+fn move_tags(workspace: &Path, from: &Path, to: &Path) -> Result<(), OrganizerError> {
+    let mut tags = load_tags(workspace);
+    if let Some(set) = tags.remove(&tag_key(from)) {
+        tags.entry(tag_key(to)).or_default().extend(set);
+        save_tags(workspace, &tags)?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Moving files
+// ---------------------------------------------------------------------------
+
+/// Move a file, falling back to copy, verify and delete when the destination
+/// is on another filesystem.
+pub fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    match std::fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => copy_verify_delete(from, to),
+        Err(e) => Err(e),
+    }
+}
+
+fn copy_verify_delete(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::copy(from, to)?;
+    std::fs::File::open(to)?.sync_all()?;
+    if hash_file(from)? != hash_file(to)? {
+        let _ = std::fs::remove_file(to);
+        return Err(std::io::Error::other(format!(
+            "copy of {} did not verify",
+            from.display()
+        )));
+    }
+    std::fs::remove_file(from)
+}
+
+fn hash_file(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Directories missing between `workspace` and `dir`, outermost first.
+fn missing_dirs(workspace: &Path, dir: &Path) -> Vec<PathBuf> {
+    let mut missing: Vec<PathBuf> = dir
+        .ancestors()
+        .take_while(|d| d.starts_with(workspace) && *d != workspace && !d.exists())
+        .map(Path::to_path_buf)
+        .collect();
+    missing.reverse();
+    missing
+}
+
+fn exists(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok()
+}
+
+// ---------------------------------------------------------------------------
+// Apply and undo
+// ---------------------------------------------------------------------------
+
+/// Outcome of applying or undoing a plan.
+#[derive(Debug, Default)]
+pub struct RunReport {
+    pub done: usize,
+    /// Entries finished by an earlier, interrupted run.
+    pub already_done: usize,
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+impl RunReport {
+    pub fn render(&self, verb: &str, plan: &OrganizePlan) -> String {
+        let mut out = format!(
+            "Plan {} {}: {} files {}",
+            plan.id, plan.state, self.done, verb
+        );
+        if self.already_done > 0 {
+            out.push_str(&format!(
+                " ({} already done before the interruption)",
+                self.already_done
+            ));
+        }
+        out.push_str(&format!(", {} skipped.\n", self.skipped.len()));
+        for (path, reason) in &self.skipped {
+            out.push_str(&format!("  SKIP  {}: {}\n", display(path), reason));
+        }
+        out
+    }
+}
+
+/// Perform a stored plan, resuming from its journal if an earlier run was
+/// interrupted.
+pub fn apply_plan(workspace: &Path, id: &str) -> Result<(OrganizePlan, RunReport), OrganizerError> {
+    let mut plan = load_plan(workspace, id)?;
+    if matches!(plan.state, PlanState::Applied | PlanState::Undone) {
+        return Err(OrganizerError::InvalidState {
+            id: plan.id,
+            state: plan.state.to_string(),
+            action: "applied",
+        });
+    }
+    plan.state = PlanState::Applying;
+    save_plan(workspace, &plan)?;
+
+    let mut journal = Journal::open(workspace, id)?;
+    let latest: HashMap<usize, JournalRecord> = journal
+        .latest()
+        .into_iter()
+        .map(|(i, r)| (i, r.clone()))
+        .collect();
+    let mut report = RunReport::default();
+
+    for (index, entry) in plan.entries.iter().enumerate() {
+        match latest.get(&index) {
+            Some(JournalRecord::Moved { .. } | JournalRecord::Tagged { .. }) => {
+                report.already_done += 1;
+                continue;
+            }
+            Some(JournalRecord::Skipped { reason, .. }) => {
+                report.skipped.push((entry.source.clone(), reason.clone()));
+                continue;
+            }
+            Some(JournalRecord::Started {
+                from, to, backup, ..
+            }) => {
+                // Interrupted mid-move: finish or restart it.
+                let (from, to) = (workspace.join(from), workspace.join(to));
+                if !exists(&from) && exists(&to) {
+                    move_tags(workspace, &entry.source, &relative_to(workspace, &to))?;
+                    journal.append(JournalRecord::Moved { index })?;
+                    report.done += 1;
+                    continue;
+                }
+                if exists(&from) && exists(&to) {
+                    // A cross-filesystem copy was cut short. The destination
+                    // was free (or backed up) when the move started, so it
+                    // is our partial copy.
+                    if hash_file(&from)? == hash_file(&to)? {
+                        std::fs::remove_file(&from)?;
+                        journal.append(JournalRecord::Moved { index })?;
+                        report.done += 1;
+                        continue;
+                    }
+                    std::fs::remove_file(&to)?;
+                }
+                if let Some(backup) = backup
+                    && exists(&workspace.join(backup))
+                    && !exists(&to)
+                {
+                    move_file(&workspace.join(backup), &to)?;
+                }
+            }
+            Some(JournalRecord::Undone { .. }) | None => {}
+        }
+
+        match entry.action {
+            EntryAction::Skip => {}
+            EntryAction::Tag => {
+                let mut tags = load_tags(workspace);
+                let set = tags.entry(tag_key(&entry.source)).or_default();
+                let added: Vec<String> = entry
+                    .tags
+                    .iter()
+                    .filter(|t| set.insert((*t).clone()))
+                    .cloned()
+                    .collect();
+                save_tags(workspace, &tags)?;
+                journal.append(JournalRecord::Tagged { index, added })?;
+                report.done += 1;
+            }
+            EntryAction::Move => match apply_move(workspace, &plan, index, &mut journal)? {
+                Ok(()) => report.done += 1,
+                Err(reason) => {
+                    journal.append(JournalRecord::Skipped {
+                        index,
+                        reason: reason.clone(),
+                    })?;
+                    report.skipped.push((entry.source.clone(), reason));
+                }
+            },
+        }
+    }
+
+    plan.state = PlanState::Applied;
+    save_plan(workspace, &plan)?;
+    Ok((plan, report))
+}
+
+fn relative_to(workspace: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(workspace).unwrap_or(path).to_path_buf()
+}
+
+/// Move one planned file. The inner `Err` is a reason to skip it.
+fn apply_move(
+    workspace: &Path,
+    plan: &OrganizePlan,
+    index: usize,
+    journal: &mut Journal,
+) -> Result<Result<(), String>, OrganizerError> {
+    let entry = &plan.entries[index];
+    let Some(destination) = &entry.destination else {
+        return Ok(Err("no destination".into()));
+    };
+    let from = workspace.join(&entry.source);
+    let mut to = workspace.join(destination);
+
+    let metadata = match std::fs::symlink_metadata(&from) {
+        Ok(m) => m,
+        Err(_) => return Ok(Err("source no longer exists".into())),
+    };
+    if metadata.file_type().is_symlink() {
+        return Ok(Err("source is now a symlink".into()));
+    }
+    let modified: DateTime<Utc> = metadata.modified().unwrap_or(SystemTime::now()).into();
+    if metadata.len() != entry.size || modified != entry.modified {
+        return Ok(Err("changed since the plan was made".into()));
+    }
+
+    let mut backup = None;
+    if exists(&to) {
+        if entry.overwrite {
+            let name = format!(
+                "{}-{}",
+                index,
+                to.file_name().unwrap_or_default().to_string_lossy()
+            );
+            backup = Some(backup_dir(workspace, &plan.id).join(name));
+        } else if plan.conflict == ConflictPolicy::Rename {
+            to = free_name(&to, &HashSet::new());
+        } else {
+            return Ok(Err(format!(
+                "{} appeared since the plan was made",
+                destination.display()
+            )));
+        }
+    }
+
+    let parent = to.parent().unwrap_or(workspace).to_path_buf();
+    // A symlinked directory on the way must not carry the file outside.
+    let existing = parent.ancestors().find(|d| d.exists()).unwrap_or(workspace);
+    if !existing
+        .canonicalize()?
+        .starts_with(workspace.canonicalize()?)
+    {
+        return Ok(Err(format!(
+            "{} resolves outside the workspace",
+            relative_to(workspace, &parent).display()
+        )));
+    }
+    let created_dirs = missing_dirs(workspace, &parent);
+    std::fs::create_dir_all(&parent)?;
+    if let Some(backup) = &backup {
+        std::fs::create_dir_all(backup.parent().unwrap_or(workspace))?;
+    }
+
+    journal.append(JournalRecord::Started {
+        index,
+        from: entry.source.clone(),
+        to: relative_to(workspace, &to),
+        backup: backup.as_deref().map(|b| relative_to(workspace, b)),
+        created_dirs: created_dirs
+            .iter()
+            .map(|d| relative_to(workspace, d))
+            .collect(),
+    })?;
+    if let Some(backup) = &backup {
+        move_file(&to, backup)?;
+    }
+    move_file(&from, &to)?;
+    move_tags(workspace, &entry.source, &relative_to(workspace, &to))?;
+    journal.append(JournalRecord::Moved { index })?;
+    Ok(Ok(()))
+}
+
+/// Reverse an applied (or partially applied) plan using its journal. With no
+/// `id`, the most recent plan that moved anything is undone.
+pub fn undo_plan(
+    workspace: &Path,
+    id: Option<&str>,
+) -> Result<(OrganizePlan, RunReport), OrganizerError> {
+    let mut plan = match id {
+        Some(id) => load_plan(workspace, id)?,
+        None => list_plans(workspace)
+            .into_iter()
+            .find(|p| matches!(p.state, PlanState::Applied | PlanState::Applying))
+            .ok_or(OrganizerError::NothingToUndo)?,
+    };
+    if !matches!(plan.state, PlanState::Applied | PlanState::Applying) {
+        return Err(OrganizerError::InvalidState {
+            id: plan.id,
+            state: plan.state.to_string(),
+            action: "undone",
+        });
+    }
+
+    let mut journal = Journal::open(workspace, &plan.id)?;
+    let latest: Vec<(usize, JournalRecord)> = {
+        let map = journal.latest();
+        let mut order: Vec<usize> = Vec::new();
+        for record in &journal.records {
+            let index = record.index();
+            order.retain(|i| *i != index);
+            order.push(index);
+        }
+        order
+            .into_iter()
+            .rev()
+            .map(|i| (i, map[&i].clone()))
+            .collect()
+    };
+    let mut report = RunReport::default();
+
+    for (index, record) in latest {
+        let source = plan
+            .entries
+            .get(index)
+            .map(|e| e.source.clone())
+            .unwrap_or_default();
+        match record {
+            JournalRecord::Undone { .. } => report.already_done += 1,
+            JournalRecord::Skipped { .. } => {}
+            JournalRecord::Tagged { added, .. } => {
+                let mut tags = load_tags(workspace);
+                let key = tag_key(&source);
+                if let Some(set) = tags.get_mut(&key) {
+                    for tag in &added {
+                        set.remove(tag);
+                    }
+                    if set.is_empty() {
+                        tags.remove(&key);
+                    }
+                }
+                save_tags(workspace, &tags)?;
+                journal.append(JournalRecord::Undone { index })?;
+                report.done += 1;
+            }
+            JournalRecord::Moved { .. } | JournalRecord::Started { .. } => {
+                let Some(JournalRecord::Started {
+                    from,
+                    to,
+                    backup,
+                    created_dirs,
+                    ..
+                }) = journal.started(index).cloned()
+                else {
+                    continue;
+                };
+                let (abs_from, abs_to) = (workspace.join(&from), workspace.join(&to));
+                if exists(&abs_to) && !exists(&abs_from) {
+                    move_file(&abs_to, &abs_from)?;
+                    move_tags(workspace, &to, &from)?;
+                } else if exists(&abs_from) && !exists(&abs_to) {
+                    // Interrupted before the file moved; nothing to put back.
+                } else if exists(&abs_from) {
+                    report
+                        .skipped
+                        .push((from, format!("{} is occupied again", source.display())));
+                    continue;
+                } else {
+                    report
+                        .skipped
+                        .push((to, "file is no longer at its destination".into()));
+                    continue;
+                }
+                if let Some(backup) = backup {
+                    let abs_backup = workspace.join(&backup);
+                    if exists(&abs_backup) && !exists(&abs_to) {
+                        move_file(&abs_backup, &abs_to)?;
+                    }
+                }
+                for dir in created_dirs.iter().rev() {
+                    // Only removes directories that are empty again.
+                    let _ = std::fs::remove_dir(workspace.join(dir));
+                }
+                journal.append(JournalRecord::Undone { index })?;
+                report.done += 1;
+            }
+        }
+    }
+
+    let _ = std::fs::remove_dir(backup_dir(workspace, &plan.id));
+    plan.state = PlanState::Undone;
+    save_plan(workspace, &plan)?;
+    Ok((plan, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_organizer::profile::{OrganizeRule, RuleMatch};
+    use tempfile::TempDir;
+
+    fn workspace() -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let ws = dir.path().canonicalize().unwrap();
+        (dir, ws)
+    }
+
+    fn profile(to: &str) -> OrganizerProfile {
+        OrganizerProfile {
+            name: "test".into(),
+            description: None,
+            rules: vec![
+                OrganizeRule {
+                    name: Some("notes".into()),
+                    matcher: RuleMatch {
+                        extensions: vec!["md".into()],
+                        ..Default::default()
+                    },
+                    action: RuleAction::Tag {
+                        tags: vec!["notes".into()],
+                    },
+                },
+                OrganizeRule {
+                    name: Some("docs".into()),
+                    matcher: RuleMatch {
+                        extensions: vec!["txt".into()],
+                        ..Default::default()
+                    },
+                    action: RuleAction::Move {
+                        to: to.into(),
+                        rename: None,
+                    },
+                },
+            ],
+            conflict: ConflictPolicy::Skip,
+            recursive: false,
+        }
+    }
+
+    #[test]
+    fn test_plan_apply_and_undo_restore_layout() {
+        let (_dir, ws) = workspace();
+        std::fs::write(ws.join("a.txt"), "a").unwrap();
+        std::fs::write(ws.join("b.md"), "b").unwrap();
+        std::fs::write(ws.join("c.bin"), "c").unwrap();
+
+        let plan = build_plan(&ws, &ws, &profile("Docs/{year}"), ConflictPolicy::Skip).unwrap();
+        assert_eq!(plan.count(EntryAction::Move), 1);
+        assert_eq!(plan.count(EntryAction::Tag), 1);
+        assert_eq!(plan.unmatched, 1);
+        // Planning moves nothing.
+        assert!(ws.join("a.txt").exists());
+
+        let (plan, report) = apply_plan(&ws, &plan.id).unwrap();
+        assert_eq!(plan.state, PlanState::Applied);
+        assert_eq!(report.done, 2);
+        let dest = ws.join(
+            plan.entries
+                .iter()
+                .find_map(|e| e.destination.clone())
+                .unwrap(),
+        );
+        assert!(dest.exists() && !ws.join("a.txt").exists());
+        assert!(load_tags(&ws)["b.md"].contains("notes"));
+
+        let (plan, report) = undo_plan(&ws, None).unwrap();
+        assert_eq!(plan.state, PlanState::Undone);
+        assert_eq!(report.done, 2);
+        assert!(ws.join("a.txt").exists());
+        assert!(!ws.join("Docs").exists());
+        assert!(load_tags(&ws).is_empty());
+    }
+
+    #[test]
+    fn test_conflict_policies() {
+        let (_dir, ws) = workspace();
+        std::fs::create_dir(ws.join("Docs")).unwrap();
+        std::fs::write(ws.join("Docs/a.txt"), "old").unwrap();
+        std::fs::write(ws.join("a.txt"), "new").unwrap();
+
+        let skip = build_plan(&ws, &ws, &profile("Docs"), ConflictPolicy::Skip).unwrap();
+        assert_eq!(skip.count(EntryAction::Skip), 1);
+        assert_eq!(skip.conflicts(), 1);
+
+        let rename = build_plan(&ws, &ws, &profile("Docs"), ConflictPolicy::Rename).unwrap();
+        assert_eq!(
+            rename.entries[0].destination.as_deref(),
+            Some(Path::new("Docs/a-1.txt"))
+        );
+
+        let overwrite = build_plan(&ws, &ws, &profile("Docs"), ConflictPolicy::Overwrite).unwrap();
+        assert_eq!(overwrite.overwrites(), 1);
+        apply_plan(&ws, &overwrite.id).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(ws.join("Docs/a.txt")).unwrap(),
+            "new"
+        );
+        undo_plan(&ws, Some(&overwrite.id)).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(ws.join("Docs/a.txt")).unwrap(),
+            "old"
+        );
+        assert_eq!(std::fs::read_to_string(ws.join("a.txt")).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_interrupted_apply_resumes_from_journal() {
+        let (_dir, ws) = workspace();
+        std::fs::write(ws.join("a.txt"), "a").unwrap();
+        std::fs::write(ws.join("b.txt"), "b").unwrap();
+        let plan = build_plan(&ws, &ws, &profile("Docs"), ConflictPolicy::Skip).unwrap();
+
+        // Simulate a crash after the first file moved but before it was
+        // journaled as done.
+        let mut journal = Journal::open(&ws, &plan.id).unwrap();
+        journal
+            .append(JournalRecord::Started {
+                index: 0,
+                from: "a.txt".into(),
+                to: "Docs/a.txt".into(),
+                backup: None,
+                created_dirs: vec!["Docs".into()],
+            })
+            .unwrap();
+        std::fs::create_dir(ws.join("Docs")).unwrap();
+        std::fs::rename(ws.join("a.txt"), ws.join("Docs/a.txt")).unwrap();
+
+        let (plan, report) = apply_plan(&ws, &plan.id).unwrap();
+        assert_eq!(report.done, 2);
+        assert!(ws.join("Docs/a.txt").exists() && ws.join("Docs/b.txt").exists());
+        undo_plan(&ws, Some(&plan.id)).unwrap();
+        assert!(ws.join("a.txt").exists() && ws.join("b.txt").exists());
+        assert!(!ws.join("Docs").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_not_followed() {
+        let (_dir, ws) = workspace();
+        std::fs::create_dir(ws.join("real")).unwrap();
+        std::fs::write(ws.join("real/target.txt"), "t").unwrap();
+        std::os::unix::fs::symlink(ws.join("real/target.txt"), ws.join("link.txt")).unwrap();
+
+        let plan = build_plan(&ws, &ws, &profile("Docs"), ConflictPolicy::Skip).unwrap();
+        assert_eq!(plan.count(EntryAction::Move), 0);
+        assert_eq!(
+            plan.entries[0].note.as_deref(),
+            Some("symlink, not followed")
+        );
+        apply_plan(&ws, &plan.id).unwrap();
+        assert!(ws.join("real/target.txt").exists());
+    }
+}
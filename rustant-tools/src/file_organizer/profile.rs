@@ -0,0 +1,601 @@
+//! Declarative organization profiles: rules that match files and say where
+//! they should go.
+//!
+//! A profile is an ordered list of rules. The first rule whose `match` fits a
+//! file decides its action; files no rule matches are left alone.
+
+use chrono::{DateTime, Datelike, Local};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+/// Name of the built-in profile that groups files into per-extension folders.
+pub const BY_EXTENSION: &str = "by_extension";
+
+/// What to do when a planned destination already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Leave the file where it is.
+    #[default]
+    Skip,
+    /// Move it under a free name such as `report-1.pdf`.
+    Rename,
+    /// Replace the existing file. The replaced file is kept for undo.
+    Overwrite,
+}
+
+impl ConflictPolicy {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "skip" => Some(Self::Skip),
+            "rename" => Some(Self::Rename),
+            "overwrite" => Some(Self::Overwrite),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::Rename => "rename",
+            Self::Overwrite => "overwrite",
+        }
+    }
+}
+
+/// A file size given as a byte count or a string such as `"10MB"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SizeSpec {
+    Bytes(u64),
+    Text(String),
+}
+
+impl SizeSpec {
+    pub fn bytes(&self) -> Option<u64> {
+        match self {
+            Self::Bytes(n) => Some(*n),
+            Self::Text(s) => parse_size(s),
+        }
+    }
+}
+
+/// Parse sizes like `512`, `10KB`, `1.5 MB` or `2GiB` (units are powers of 1024).
+pub fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1u64,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        "t" | "tb" | "tib" => 1 << 40,
+        _ => return None,
+    };
+    Some((number * multiplier as f64) as u64)
+}
+
+/// Conditions a file must meet for a rule to apply. All given conditions must
+/// hold; an empty match applies to every file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleMatch {
+    /// Glob matched against the file name, or against the path relative to
+    /// the organized directory when it contains `/`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glob: Option<String>,
+    /// Extensions without the dot, compared case-insensitively.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<String>,
+    /// Minimum days since last modification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_age_days: Option<u64>,
+    /// Maximum days since last modification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<SizeSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<SizeSpec>,
+    /// Content types such as `image/*` or `application/pdf`, detected from
+    /// the file's leading bytes with the extension as fallback.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content_types: Vec<String>,
+}
+
+/// What a matching rule does with a file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum RuleAction {
+    /// Move into `to` (a directory template relative to the organized
+    /// directory), optionally renaming with `rename`.
+    Move {
+        to: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rename: Option<String>,
+    },
+    /// Rename in place using a name template.
+    Rename { pattern: String },
+    /// Record tags for the file without moving it.
+    Tag { tags: Vec<String> },
+    /// Leave the file alone, and stop later rules from matching it.
+    Skip,
+}
+
+/// One profile rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OrganizeRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, rename = "match")]
+    pub matcher: RuleMatch,
+    pub action: RuleAction,
+}
+
+impl OrganizeRule {
+    /// Label used in plans.
+    pub fn label(&self, index: usize) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("rule {}", index + 1))
+    }
+}
+
+/// A named, declarative organization profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OrganizerProfile {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub rules: Vec<OrganizeRule>,
+    /// Default conflict policy; a call may override it.
+    #[serde(default)]
+    pub conflict: ConflictPolicy,
+    /// Also organize files in subdirectories.
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+impl OrganizerProfile {
+    /// The built-in profile: every file into a folder named after its extension.
+    pub fn by_extension() -> Self {
+        Self {
+            name: BY_EXTENSION.to_string(),
+            description: Some("Group files into folders named after their extension".into()),
+            rules: vec![OrganizeRule {
+                name: Some("by extension".into()),
+                matcher: RuleMatch::default(),
+                action: RuleAction::Move {
+                    to: "{ext}".into(),
+                    rename: None,
+                },
+            }],
+            conflict: ConflictPolicy::Skip,
+            recursive: false,
+        }
+    }
+
+    /// Check that the profile is usable, naming the first broken field.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty()
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!(
+                "profile name '{}' must be letters, digits, '_' or '-'",
+                self.name
+            ));
+        }
+        if self.rules.is_empty() {
+            return Err("profile has no rules".into());
+        }
+        for (i, rule) in self.rules.iter().enumerate() {
+            let field = |f: &str| format!("rules[{}].{}", i, f);
+            if let Some(glob) = &rule.matcher.glob {
+                globset::Glob::new(glob).map_err(|e| format!("{}: {}", field("match.glob"), e))?;
+            }
+            for (name, size) in [
+                ("match.min_size", &rule.matcher.min_size),
+                ("match.max_size", &rule.matcher.max_size),
+            ] {
+                if let Some(size) = size
+                    && size.bytes().is_none()
+                {
+                    return Err(format!("{}: unrecognised size {:?}", field(name), size));
+                }
+            }
+            match &rule.action {
+                RuleAction::Move { to, rename } => {
+                    check_template(to, true)
+                        .map_err(|e| format!("{}: {}", field("action.to"), e))?;
+                    if let Some(rename) = rename {
+                        check_template(rename, false)
+                            .map_err(|e| format!("{}: {}", field("action.rename"), e))?;
+                    }
+                }
+                RuleAction::Rename { pattern } => {
+                    check_template(pattern, false)
+                        .map_err(|e| format!("{}: {}", field("action.pattern"), e))?;
+                }
+                RuleAction::Tag { tags } if tags.is_empty() => {
+                    return Err(format!("{}: no tags given", field("action.tags")));
+                }
+                RuleAction::Tag { .. } | RuleAction::Skip => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// The first rule matching `file`, with its index.
+    pub fn rule_for(&self, file: &FileFacts, now: SystemTime) -> Option<(usize, &OrganizeRule)> {
+        self.rules
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.matcher.matches(file, now))
+    }
+}
+
+/// What rules can see about a file.
+#[derive(Debug, Clone)]
+pub struct FileFacts {
+    /// Path relative to the organized directory.
+    pub relative: PathBuf,
+    pub size: u64,
+    pub modified: SystemTime,
+    pub content_type: &'static str,
+}
+
+impl FileFacts {
+    /// Gather facts for `path`, reading its first bytes to sniff the content type.
+    pub fn read(root: &Path, path: &Path, metadata: &std::fs::Metadata) -> Self {
+        Self {
+            relative: path.strip_prefix(root).unwrap_or(path).to_path_buf(),
+            size: metadata.len(),
+            modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            content_type: content_type(path),
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        self.relative
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn extension(&self) -> Option<String> {
+        self.relative
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+    }
+
+    fn age_days(&self, now: SystemTime) -> u64 {
+        now.duration_since(self.modified)
+            .map(|d| d.as_secs() / 86_400)
+            .unwrap_or(0)
+    }
+}
+
+impl RuleMatch {
+    pub fn matches(&self, file: &FileFacts, now: SystemTime) -> bool {
+        if let Some(glob) = &self.glob {
+            let Ok(glob) = globset::Glob::new(glob) else {
+                return false;
+            };
+            let matcher = glob.compile_matcher();
+            let subject = if glob.glob().contains('/') {
+                file.relative.to_string_lossy().replace('\\', "/")
+            } else {
+                file.file_name()
+            };
+            if !matcher.is_match(subject) {
+                return false;
+            }
+        }
+        if !self.extensions.is_empty() {
+            let Some(ext) = file.extension() else {
+                return false;
+            };
+            if !self
+                .extensions
+                .iter()
+                .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&ext))
+            {
+                return false;
+            }
+        }
+        let age = file.age_days(now);
+        if self.min_age_days.is_some_and(|min| age < min)
+            || self.max_age_days.is_some_and(|max| age > max)
+        {
+            return false;
+        }
+        if self
+            .min_size
+            .as_ref()
+            .and_then(SizeSpec::bytes)
+            .is_some_and(|min| file.size < min)
+            || self
+                .max_size
+                .as_ref()
+                .and_then(SizeSpec::bytes)
+                .is_some_and(|max| file.size > max)
+        {
+            return false;
+        }
+        if !self.content_types.is_empty()
+            && !self
+                .content_types
+                .iter()
+                .any(|pattern| content_type_matches(pattern, file.content_type))
+        {
+            return false;
+        }
+        true
+    }
+}
+
+fn content_type_matches(pattern: &str, content_type: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(family) => content_type
+            .split('/')
+            .next()
+            .is_some_and(|f| f.eq_ignore_ascii_case(family)),
+        None => pattern.eq_ignore_ascii_case(content_type),
+    }
+}
+
+/// Detect a file's content type from its leading bytes, falling back to the
+/// extension for formats without a signature.
+pub fn content_type(path: &Path) -> &'static str {
+    let mut head = [0u8; 16];
+    let read = std::fs::File::open(path)
+        .and_then(|mut f| f.read(&mut head))
+        .unwrap_or(0);
+    let head = &head[..read];
+    let sniffed = match head {
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [
+            b'R',
+            b'I',
+            b'F',
+            b'F',
+            _,
+            _,
+            _,
+            _,
+            b'W',
+            b'E',
+            b'B',
+            b'P',
+            ..,
+        ] => Some("image/webp"),
+        [
+            b'R',
+            b'I',
+            b'F',
+            b'F',
+            _,
+            _,
+            _,
+            _,
+            b'W',
+            b'A',
+            b'V',
+            b'E',
+            ..,
+        ] => Some("audio/wav"),
+        [
+            _,
+            _,
+            _,
+            _,
+            b'f',
+            b't',
+            b'y',
+            b'p',
+            b'h',
+            b'e',
+            b'i',
+            b'c',
+            ..,
+        ] => Some("image/heic"),
+        [_, _, _, _, b'f', b't', b'y', b'p', b'q', b't', ..] => Some("video/quicktime"),
+        [_, _, _, _, b'f', b't', b'y', b'p', b'M', b'4', b'A', ..] => Some("audio/mp4"),
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => Some("video/mp4"),
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => Some("video/webm"),
+        [b'I', b'D', b'3', ..] | [0xFF, 0xFB, ..] => Some("audio/mpeg"),
+        [b'f', b'L', b'a', b'C', ..] => Some("audio/flac"),
+        [b'O', b'g', b'g', b'S', ..] => Some("audio/ogg"),
+        [b'%', b'P', b'D', b'F', ..] => Some("application/pdf"),
+        [b'P', b'K', 0x03, 0x04, ..] => Some("application/zip"),
+        [0x1F, 0x8B, ..] => Some("application/gzip"),
+        [b'7', b'z', 0xBC, 0xAF, ..] => Some("application/x-7z-compressed"),
+        [b'R', b'a', b'r', b'!', ..] => Some("application/vnd.rar"),
+        [0x7F, b'E', b'L', b'F', ..] => Some("application/x-executable"),
+        _ => None,
+    };
+    if let Some(sniffed) = sniffed {
+        // Office documents are zip files; the extension says which kind.
+        if sniffed != "application/zip" {
+            return sniffed;
+        }
+    }
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "svg" => "image/svg+xml",
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "txt" | "log" | "rs" | "py" | "js" | "ts" | "toml" | "yaml" | "yml" => "text/plain",
+        _ if sniffed.is_some() => "application/zip",
+        _ if !head.is_empty() && std::str::from_utf8(head).is_ok() => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Expand a destination or name template for `file`.
+///
+/// Placeholders: `{name}` (file name), `{stem}`, `{ext}` (`no_extension` when
+/// missing), `{year}`, `{month}`, `{day}`, `{date}` (`YYYY-MM-DD`) from the
+/// modification time, and `{type}` (content type family such as `image`).
+pub fn expand_template(template: &str, file: &FileFacts) -> String {
+    let modified: DateTime<Local> = file.modified.into();
+    let name = file.file_name();
+    let stem = file
+        .relative
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| name.clone());
+    let ext = file.extension();
+    let family = file.content_type.split('/').next().unwrap_or("other");
+    template
+        .replace("{name}", &name)
+        .replace("{stem}", &stem)
+        .replace("{ext}", ext.as_deref().unwrap_or("no_extension"))
+        .replace("{year}", &format!("{:04}", modified.year()))
+        .replace("{month}", &format!("{:02}", modified.month()))
+        .replace("{day}", &format!("{:02}", modified.day()))
+        .replace("{date}", &modified.format("%Y-%m-%d").to_string())
+        .replace("{type}", family)
+}
+
+/// A rename template must end up as a bare name whose extension is kept
+/// unless the template names one itself.
+pub fn expand_name(template: &str, file: &FileFacts) -> String {
+    let name = expand_template(template, file);
+    match file.extension() {
+        Some(ext) if !template.contains("{ext}") && !template.contains("{name}") => {
+            if Path::new(&name).extension().is_some() {
+                name
+            } else {
+                format!("{}.{}", name, ext)
+            }
+        }
+        _ => name,
+    }
+}
+
+fn check_template(template: &str, directory: bool) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("empty template".into());
+    }
+    let path = Path::new(template);
+    if path.is_absolute() {
+        return Err(format!("'{}' must be relative", template));
+    }
+    if path
+        .components()
+        .any(|c| matches!(c, Component::ParentDir | Component::Prefix(_)))
+    {
+        return Err(format!("'{}' must not contain '..'", template));
+    }
+    if !directory && template.contains(['/', '\\']) {
+        return Err(format!("'{}' must be a file name, not a path", template));
+    }
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            return Err(format!("unclosed '{{' in '{}'", template));
+        };
+        let key = &rest[start + 1..start + end];
+        if !matches!(
+            key,
+            "name" | "stem" | "ext" | "year" | "month" | "day" | "date" | "type"
+        ) {
+            return Err(format!("unknown placeholder '{{{}}}'", key));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn facts(name: &str, size: u64, age_days: u64) -> FileFacts {
+        FileFacts {
+            relative: PathBuf::from(name),
+            size,
+            modified: SystemTime::now() - Duration::from_secs(age_days * 86_400),
+            content_type: "image/png",
+        }
+    }
+
+    #[test]
+    fn test_rule_match_conditions() {
+        let rule: RuleMatch = serde_json::from_value(serde_json::json!({
+            "extensions": ["PNG", ".jpg"],
+            "min_age_days": 30,
+            "max_size": "1MB",
+            "content_types": ["image/*"]
+        }))
+        .unwrap();
+        let now = SystemTime::now();
+        assert!(rule.matches(&facts("a.png", 1024, 40), now));
+        assert!(!rule.matches(&facts("a.png", 1024, 3), now));
+        assert!(!rule.matches(&facts("a.png", 2 << 20, 40), now));
+        assert!(!rule.matches(&facts("a.gif", 1024, 40), now));
+
+        let glob = RuleMatch {
+            glob: Some("shots/*.png".into()),
+            ..Default::default()
+        };
+        assert!(glob.matches(&facts("shots/a.png", 1, 0), now));
+        assert!(!glob.matches(&facts("a.png", 1, 0), now));
+    }
+
+    #[test]
+    fn test_templates_and_validation() {
+        let file = facts("IMG_001.png", 1, 0);
+        let dir = expand_template("Pictures/{year}/{type}", &file);
+        assert!(dir.starts_with("Pictures/20") && dir.ends_with("/image"));
+        assert!(expand_name("{date}-photo", &file).ends_with("-photo.png"));
+        assert_eq!(expand_name("{stem}-x.{ext}", &file), "IMG_001-x.png");
+
+        let mut profile = OrganizerProfile::by_extension();
+        assert!(profile.validate().is_ok());
+        profile.rules[0].action = RuleAction::Move {
+            to: "../outside".into(),
+            rename: None,
+        };
+        assert!(
+            profile
+                .validate()
+                .unwrap_err()
+                .contains("rules[0].action.to")
+        );
+        profile.rules[0].action = RuleAction::Rename {
+            pattern: "{when}".into(),
+        };
+        assert!(
+            profile
+                .validate()
+                .unwrap_err()
+                .contains("unknown placeholder")
+        );
+        assert_eq!(parse_size("1.5 KB"), Some(1536));
+        assert_eq!(parse_size("ten"), None);
+    }
+}
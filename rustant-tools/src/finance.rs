@@ -6,7 +6,7 @@ use rustant_core::error::ToolError;
 use rustant_core::types::{RiskLevel, ToolOutput};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::registry::Tool;
 
@@ -92,6 +92,54 @@ impl FinanceTool {
     }
 }
 
+/// Briefing summary: month-to-date totals and budgets at or above 80% of their limit.
+pub fn finance_briefing(workspace: &Path, today: chrono::NaiveDate) -> String {
+    let state = FinanceTool::new(workspace.to_path_buf()).load_state();
+    let month_start = today.with_day(1).unwrap_or(today);
+    let this_month: Vec<&Transaction> = state
+        .transactions
+        .iter()
+        .filter(|t| t.date.date_naive() >= month_start && t.date.date_naive() <= today)
+        .collect();
+    if this_month.is_empty() {
+        return "No transactions recorded this month.".into();
+    }
+    let spent: f64 = this_month
+        .iter()
+        .filter(|t| !t.is_income)
+        .map(|t| t.amount)
+        .sum();
+    let income: f64 = this_month
+        .iter()
+        .filter(|t| t.is_income)
+        .map(|t| t.amount)
+        .sum();
+    let mut out = format!(
+        "Month to date: ${:.2} spent, ${:.2} income.\n",
+        spent, income
+    );
+    for budget in &state.budgets {
+        let used: f64 = this_month
+            .iter()
+            .filter(|t| !t.is_income && t.category == budget.category)
+            .map(|t| t.amount)
+            .sum();
+        if budget.limit <= 0.0 || used < budget.limit * 0.8 {
+            continue;
+        }
+        let status = if used >= budget.limit {
+            "over budget"
+        } else {
+            "near limit"
+        };
+        out.push_str(&format!(
+            "- {}: ${:.2} of ${:.2} ({})\n",
+            budget.category, used, budget.limit, status
+        ));
+    }
+    out
+}
+
 #[async_trait]
 impl Tool for FinanceTool {
     fn name(&self) -> &str {
@@ -325,6 +373,34 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[tokio::test]
+    async fn test_finance_briefing_flags_budgets_near_limit() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let today = Utc::now().date_naive();
+        assert_eq!(
+            finance_briefing(&workspace, today),
+            "No transactions recorded this month."
+        );
+        let tool = FinanceTool::new(workspace.clone());
+        for (category, limit) in [("food", 100.0), ("fun", 500.0)] {
+            tool.execute(json!({"action": "set_budget", "category": category, "limit": limit}))
+                .await
+                .unwrap();
+        }
+        for (category, amount) in [("food", 90.0), ("fun", 20.0)] {
+            tool.execute(
+                json!({"action": "add_transaction", "amount": amount, "category": category}),
+            )
+            .await
+            .unwrap();
+        }
+        let briefing = finance_briefing(&workspace, today);
+        assert!(briefing.starts_with("Month to date: $110.00 spent"));
+        assert!(briefing.contains("- food: $90.00 of $100.00 (near limit)"));
+        assert!(!briefing.contains("fun"));
+    }
+
     #[tokio::test]
     async fn test_finance_add_list() {
         let dir = TempDir::new().unwrap();
@@ -6,7 +6,8 @@ use rustant_core::error::ToolError;
 use rustant_core::types::{RiskLevel, ToolOutput};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use crate::registry::Tool;
 
@@ -125,6 +126,24 @@ impl FlashcardsTool {
     }
 }
 
+/// Briefing summary of the cards due for review by `until`, grouped by deck.
+pub fn due_cards_briefing(workspace: &Path, until: DateTime<Utc>) -> String {
+    let state = FlashcardsTool::new(workspace.to_path_buf()).load_state();
+    let mut by_deck: BTreeMap<&str, usize> = BTreeMap::new();
+    for card in state.cards.iter().filter(|c| c.next_review <= until) {
+        *by_deck.entry(card.deck.as_str()).or_default() += 1;
+    }
+    let total: usize = by_deck.values().sum();
+    if total == 0 {
+        return "No cards due.".into();
+    }
+    let mut out = format!("{} card(s) due for review.\n", total);
+    for (deck, count) in by_deck {
+        out.push_str(&format!("- {}: {}\n", deck, count));
+    }
+    out
+}
+
 #[async_trait]
 impl Tool for FlashcardsTool {
     fn name(&self) -> &str {
@@ -262,6 +281,28 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[tokio::test]
+    async fn test_due_cards_briefing_groups_by_deck() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        assert_eq!(due_cards_briefing(&workspace, Utc::now()), "No cards due.");
+        let tool = FlashcardsTool::new(workspace.clone());
+        for (deck, front) in [
+            ("rust", "Borrowing?"),
+            ("rust", "Lifetimes?"),
+            ("go", "Goroutines?"),
+        ] {
+            tool.execute(
+                json!({"action": "add_card", "deck": deck, "front": front, "back": "..."}),
+            )
+            .await
+            .unwrap();
+        }
+        let briefing = due_cards_briefing(&workspace, Utc::now() + ChronoDuration::days(1));
+        assert!(briefing.starts_with("3 card(s) due"));
+        assert!(briefing.contains("- go: 1\n- rust: 2"));
+    }
+
     #[test]
     fn test_sm2_easy_increases_interval() {
         let mut card = Flashcard::new(1, "test", "Q", "A");
@@ -1,12 +1,51 @@
 //! Git integration tools: status, diff, and commit.
 
+use crate::diff_summary::DiffSummary;
 use crate::registry::Tool;
 use async_trait::async_trait;
 use rustant_core::error::ToolError;
-use rustant_core::types::{RiskLevel, ToolOutput};
+use rustant_core::types::{RiskLevel, ToolErrorKind, ToolFailure, ToolOutput};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use tracing::debug;
 
+/// Raw diff kept after the summary in `git_diff`'s summarize mode.
+const MAX_SUMMARY_RAW_DIFF_BYTES: usize = 16 * 1024;
+
+/// Classify a failed git command from its stderr.
+fn git_failure(stderr: &str) -> ToolFailure {
+    let lower = stderr.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+    if has(&["not a git repository"]) {
+        ToolFailure::new(ToolErrorKind::NotFound).with_remediation(
+            "The workspace is not a git repository; work inside one or run `git init`.",
+        )
+    } else if has(&[
+        "unknown revision",
+        "bad revision",
+        "ambiguous argument",
+        "did not match any",
+    ]) {
+        ToolFailure::new(ToolErrorKind::NotFound)
+            .with_remediation("Check the revision or path with git_status or `git log` first.")
+    } else if has(&["index.lock", "another git process"]) {
+        ToolFailure::new(ToolErrorKind::Conflict)
+            .with_remediation("Another git process holds the index lock; let it finish first.")
+    } else if has(&["nothing to commit", "no changes added to commit"]) {
+        ToolFailure::new(ToolErrorKind::Conflict)
+            .with_remediation("There are no staged changes; stage files or skip the commit.")
+    } else if has(&["conflict", "non-fast-forward", "[rejected]"]) {
+        ToolFailure::new(ToolErrorKind::Conflict)
+            .with_remediation("Inspect the conflicting changes with git_status and git_diff.")
+    } else if has(&["please tell me who you are", "user.email"]) {
+        ToolFailure::new(ToolErrorKind::Internal).with_remediation(
+            "git has no author identity; ask the user to set user.name and user.email.",
+        )
+    } else {
+        ToolFailure::new(ToolErrorKind::classify(stderr))
+    }
+}
+
 /// Show git repository status.
 pub struct GitStatusTool {
     workspace: PathBuf,
@@ -23,19 +62,23 @@ impl GitStatusTool {
             .current_dir(&self.workspace)
             .output()
             .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "git".into(),
-                message: format!("Failed to run git: {}", e),
+            .map_err(|e| {
+                ToolError::failed(
+                    "git",
+                    format!("Failed to run git: {}", e),
+                    ToolFailure::from_io(&e),
+                )
             })?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
         if !output.status.success() {
-            return Err(ToolError::ExecutionFailed {
-                name: "git".into(),
-                message: format!("git {} failed: {}", args.join(" "), stderr),
-            });
+            return Err(ToolError::failed(
+                "git",
+                format!("git {} failed: {}", args.join(" "), stderr),
+                git_failure(&stderr),
+            ));
         }
 
         Ok(if stdout.is_empty() { stderr } else { stdout })
@@ -92,19 +135,69 @@ impl GitDiffTool {
         Self { workspace }
     }
 
-    async fn run_git(&self, args: &[&str]) -> Result<String, ToolError> {
-        let output = tokio::process::Command::new("git")
+    async fn git_output(&self, args: &[&str]) -> Result<std::process::Output, ToolError> {
+        tokio::process::Command::new("git")
             .args(args)
             .current_dir(&self.workspace)
             .output()
             .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "git_diff".into(),
-                message: format!("Failed to run git: {}", e),
-            })?;
+            .map_err(|e| {
+                ToolError::failed(
+                    "git_diff",
+                    format!("Failed to run git: {}", e),
+                    ToolFailure::from_io(&e),
+                )
+            })
+    }
 
+    async fn run_git(&self, args: &[&str]) -> Result<String, ToolError> {
+        let output = self.git_output(args).await?;
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
+
+    /// Post-change content of each changed file, for mapping hunks to the
+    /// symbols that enclose them.
+    async fn new_contents(
+        &self,
+        diff: &str,
+        rev: Option<&str>,
+        staged: bool,
+    ) -> HashMap<String, String> {
+        // Where the "new" side of the diff lives: the working tree, the
+        // index (`:path`) or the right-hand revision of a range.
+        let source = match rev {
+            Some(rev) => match rev.split_once("...").or_else(|| rev.split_once("..")) {
+                Some((_, "")) => Some("HEAD:".to_string()),
+                Some((_, right)) => Some(format!("{}:", right)),
+                None if staged => Some(":".to_string()),
+                None => None,
+            },
+            None if staged => Some(":".to_string()),
+            None => None,
+        };
+
+        let mut contents = HashMap::new();
+        for path in DiffSummary::content_paths(diff) {
+            let content = match &source {
+                None => tokio::fs::read_to_string(self.workspace.join(&path))
+                    .await
+                    .ok(),
+                Some(prefix) => {
+                    let spec = format!("{}{}", prefix, path);
+                    match self.git_output(&["show", &spec]).await {
+                        Ok(output) if output.status.success() => {
+                            String::from_utf8(output.stdout).ok()
+                        }
+                        _ => None,
+                    }
+                }
+            };
+            if let Some(content) = content {
+                contents.insert(path, content);
+            }
+        }
+        contents
+    }
 }
 
 #[async_trait]
@@ -114,7 +207,7 @@ impl Tool for GitDiffTool {
     }
 
     fn description(&self) -> &str {
-        "Show the diff of changes in the working tree. Optionally specify a file path to see changes for a specific file."
+        "Show the diff of changes in the working tree, the index or between revisions. Optionally specify a file path to see changes for a specific file. Set summarize to get per-file change types, the functions and types each hunk touches, risk flags and stats ahead of a size-capped raw diff."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -128,6 +221,14 @@ impl Tool for GitDiffTool {
                 "staged": {
                     "type": "boolean",
                     "description": "Show staged changes instead of unstaged. Default: false."
+                },
+                "rev": {
+                    "type": "string",
+                    "description": "Revision or range to diff, e.g. 'HEAD~3' or 'main...feature'. Default: the working tree."
+                },
+                "summarize": {
+                    "type": "boolean",
+                    "description": "Return a structured summary (files, touched symbols, risky hunks, stats) followed by a truncated raw diff. Default: false."
                 }
             }
         })
@@ -135,29 +236,93 @@ impl Tool for GitDiffTool {
 
     async fn execute(&self, args: serde_json::Value) -> Result<ToolOutput, ToolError> {
         let staged = args["staged"].as_bool().unwrap_or(false);
+        let summarize = args["summarize"].as_bool().unwrap_or(false);
         let path = args["path"].as_str();
+        let rev = args["rev"]
+            .as_str()
+            .map(str::trim)
+            .filter(|r| !r.is_empty());
+
+        if let Some(rev) = rev
+            && rev.starts_with('-')
+        {
+            return Err(ToolError::InvalidArguments {
+                name: "git_diff".into(),
+                reason: format!("'{}' is not a revision", rev),
+            });
+        }
 
         let mut git_args = vec!["diff"];
         if staged {
             git_args.push("--cached");
         }
+        if summarize {
+            git_args.push("--find-renames");
+        }
+        if let Some(rev) = rev {
+            git_args.push(rev);
+        }
         if let Some(p) = path {
             git_args.push("--");
             git_args.push(p);
         }
 
-        debug!(staged, path = ?path, "Getting git diff");
+        debug!(staged, summarize, rev = ?rev, path = ?path, "Getting git diff");
+
+        let diff = match rev {
+            // A bad revision should fail loudly rather than look like "no changes".
+            Some(rev) => {
+                let output = self.git_output(&git_args).await?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    return Err(ToolError::failed(
+                        "git_diff",
+                        format!("git diff {} failed: {}", rev, stderr.trim()),
+                        git_failure(&stderr),
+                    ));
+                }
+                String::from_utf8_lossy(&output.stdout).to_string()
+            }
+            None => self.run_git(&git_args).await?,
+        };
 
-        let diff = self.run_git(&git_args).await?;
+        if diff.trim().is_empty() {
+            let scope = match rev {
+                Some(rev) => format!("changes in {}", rev),
+                None if staged => "staged changes".to_string(),
+                None => "unstaged changes".to_string(),
+            };
+            return Ok(ToolOutput::text(format!("No {}", scope)));
+        }
+        if !summarize {
+            return Ok(ToolOutput::text(diff));
+        }
 
-        let output = if diff.trim().is_empty() {
-            let scope = if staged { "staged" } else { "unstaged" };
-            format!("No {} changes", scope)
+        let contents = self.new_contents(&diff, rev, staged).await;
+        let summary = DiffSummary::from_diff(&diff, |path| contents.get(path).cloned());
+        let mut content = summary.render();
+        content.push_str("\n--- Raw diff ---\n");
+        if diff.len() > MAX_SUMMARY_RAW_DIFF_BYTES {
+            let mut end = MAX_SUMMARY_RAW_DIFF_BYTES;
+            while !diff.is_char_boundary(end) {
+                end -= 1;
+            }
+            content.push_str(&diff[..end]);
+            content.push_str(&format!(
+                "\n... [raw diff truncated: {} of {} bytes shown; pass `path` for one file]\n",
+                end,
+                diff.len()
+            ));
         } else {
-            diff
-        };
+            content.push_str(&diff);
+        }
 
-        Ok(ToolOutput::text(output))
+        let mut output = ToolOutput::text(content);
+        output.metadata.insert(
+            "diff_summary".into(),
+            serde_json::to_value(&summary).unwrap_or(serde_json::Value::Null),
+        );
+        Ok(output)
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -181,19 +346,23 @@ impl GitCommitTool {
             .current_dir(&self.workspace)
             .output()
             .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "git_commit".into(),
-                message: format!("Failed to run git: {}", e),
+            .map_err(|e| {
+                ToolError::failed(
+                    "git_commit",
+                    format!("Failed to run git: {}", e),
+                    ToolFailure::from_io(&e),
+                )
             })?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
         if !output.status.success() {
-            return Err(ToolError::ExecutionFailed {
-                name: "git_commit".into(),
-                message: format!("git {} failed: {}", args.join(" "), stderr),
-            });
+            return Err(ToolError::failed(
+                "git_commit",
+                format!("git {} failed: {}", args.join(" "), stderr),
+                git_failure(&stderr),
+            ));
         }
 
         Ok(if stdout.is_empty() { stderr } else { stdout })
@@ -344,6 +513,62 @@ mod tests {
         assert!(result.content.contains("Updated") || result.content.contains("diff"));
     }
 
+    #[tokio::test]
+    async fn test_git_diff_summarize() {
+        let dir = setup_git_repo();
+        std::fs::write(
+            dir.path().join("lib.rs"),
+            "pub fn parse(input: &str) -> usize {\n    input.len()\n}\n",
+        )
+        .unwrap();
+        std::process::Command::new("git")
+            .args(["add", "."])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        std::process::Command::new("git")
+            .args(["commit", "-m", "Add parser"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        std::fs::write(
+            dir.path().join("lib.rs"),
+            "pub fn parse(input: &str) -> Result<usize, String> {\n    Ok(input.len())\n}\n",
+        )
+        .unwrap();
+
+        let tool = GitDiffTool::new(dir.path().to_path_buf());
+        let result = tool
+            .execute(serde_json::json!({"summarize": true}))
+            .await
+            .unwrap();
+        assert!(result.content.starts_with("Diff summary: 1 file changed"));
+        assert!(result.content.contains("modified function `parse`"));
+        assert!(result.content.contains("--- Raw diff ---"));
+        let summary = &result.metadata["diff_summary"];
+        assert_eq!(summary["files"][0]["path"], "lib.rs");
+        let risks = summary["files"][0]["risks"].as_array().unwrap();
+        assert!(risks.contains(&serde_json::json!("public_api")));
+
+        // The same change, committed, read back through a revision range.
+        std::process::Command::new("git")
+            .args(["commit", "-am", "Return a result"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        let result = tool
+            .execute(serde_json::json!({"rev": "HEAD~1..HEAD", "summarize": true}))
+            .await
+            .unwrap();
+        assert!(result.content.contains("modified function `parse`"));
+
+        let err = tool
+            .execute(serde_json::json!({"rev": "--output=x"}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments { .. }));
+    }
+
     #[tokio::test]
     async fn test_git_commit() {
         let dir = setup_git_repo();
@@ -417,7 +642,9 @@ mod tests {
         let tool = GitStatusTool::new(dir.path().to_path_buf());
         let result = tool.execute(serde_json::json!({})).await;
         // Should fail since it's not a git repo
-        assert!(result.is_err());
+        let failure = result.unwrap_err().failure();
+        assert_eq!(failure.kind, ToolErrorKind::NotFound);
+        assert!(failure.remediation_text().contains("not a git repository"));
     }
 
     #[test]
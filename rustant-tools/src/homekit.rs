@@ -2,27 +2,516 @@
 //!
 //! Uses the `shortcuts` CLI to list and run HomeKit-related shortcuts.
 //! Requires macOS 12+ with Shortcuts app configured.
+//!
+//! Accessory state, scenes, and per-accessory control go through three
+//! bridge shortcuts the user installs once:
+//!
+//! - `Rustant Home State` — outputs JSON
+//!   `{"accessories": [{"name", "room", "category", "state", "reachable"}], "scenes": [..]}`
+//! - `Rustant Home Control` — takes JSON input `{"accessory", "command", "value"}`
+//! - `Rustant Home Scene` — takes the scene name as text input
+//!
+//! The state snapshot is cached for a short TTL so repeated questions
+//! ("is the porch light on?") don't re-run the shortcut; answers note the
+//! snapshot's age and `refresh` forces a new one. Security-class
+//! accessories (locks, garage doors, alarms) are flagged to the safety
+//! guardian, which always asks for approval before they are controlled.
 
 use async_trait::async_trait;
 use rustant_core::error::ToolError;
+use rustant_core::safety::is_security_accessory;
 use rustant_core::types::{RiskLevel, ToolOutput};
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::registry::Tool;
 
+/// Shortcut that reports accessory state and scenes as JSON.
+pub const STATE_SHORTCUT: &str = "Rustant Home State";
+/// Shortcut that applies a command to a single accessory.
+pub const CONTROL_SHORTCUT: &str = "Rustant Home Control";
+/// Shortcut that activates a scene by name.
+pub const SCENE_SHORTCUT: &str = "Rustant Home Scene";
+
+/// Default lifetime of a cached state snapshot.
+const DEFAULT_STATE_TTL: Duration = Duration::from_secs(300);
+
+/// Runs macOS shortcuts. Abstracted so the tool can be exercised without
+/// the Shortcuts app.
+pub trait ShortcutRunner: Send + Sync {
+    /// List the names of all installed shortcuts.
+    fn list(&self) -> Result<Vec<String>, String>;
+    /// Run a shortcut, optionally with text input, returning its output.
+    fn run(&self, name: &str, input: Option<&str>) -> Result<String, String>;
+}
+
+/// Runs shortcuts through the `shortcuts` CLI.
+pub struct CliShortcutRunner;
+
+impl ShortcutRunner for CliShortcutRunner {
+    fn list(&self) -> Result<Vec<String>, String> {
+        let output = Command::new("shortcuts")
+            .arg("list")
+            .output()
+            .map_err(|e| format!("Failed to list shortcuts: {}", e))?;
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.to_string())
+            .collect())
+    }
+
+    fn run(&self, name: &str, input: Option<&str>) -> Result<String, String> {
+        let mut cmd = Command::new("shortcuts");
+        cmd.args(["run", name]);
+        if let Some(input) = input {
+            cmd.args(["--input-type", "text", "--input", input]);
+        }
+        let output = cmd
+            .output()
+            .map_err(|e| format!("Failed to run shortcut '{}': {}", name, e))?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(format!(
+                "Shortcut '{}' failed: {}",
+                name,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+}
+
+/// A single accessory as reported by the state shortcut.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HomeAccessory {
+    pub name: String,
+    #[serde(default)]
+    pub room: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub state: Value,
+    #[serde(default = "default_reachable")]
+    pub reachable: bool,
+}
+
+fn default_reachable() -> bool {
+    true
+}
+
+impl HomeAccessory {
+    /// Whether this accessory is a lock, garage door, or alarm.
+    pub fn is_security(&self) -> bool {
+        is_security_accessory(&self.category) || is_security_accessory(&self.name)
+    }
+
+    fn state_text(&self) -> String {
+        match &self.state {
+            Value::Null => "unknown".to_string(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// A point-in-time view of the home.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HomeSnapshot {
+    #[serde(default)]
+    pub accessories: Vec<HomeAccessory>,
+    #[serde(default)]
+    pub scenes: Vec<String>,
+}
+
+/// Whether an accessory category matches a requested category such as
+/// "lights", "light", or "all".
+fn category_matches(requested: &str, category: &str) -> bool {
+    let requested = requested.trim().to_lowercase();
+    if requested.is_empty() || requested == "all" {
+        return true;
+    }
+    let category = category.to_lowercase();
+    let singular = requested.strip_suffix('s').unwrap_or(&requested);
+    category == requested || category == singular || category.contains(singular)
+}
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m {}s", secs / 60, secs % 60)
+    }
+}
+
 /// Tool for HomeKit smart home control via macOS Shortcuts.
-pub struct HomeKitTool;
+pub struct HomeKitTool {
+    runner: Arc<dyn ShortcutRunner>,
+    cache: Mutex<Option<(Instant, HomeSnapshot)>>,
+    ttl: Duration,
+}
 
 impl Default for HomeKitTool {
     fn default() -> Self {
-        Self
+        Self::new()
     }
 }
 
 impl HomeKitTool {
     pub fn new() -> Self {
-        Self
+        Self::with_runner(Arc::new(CliShortcutRunner))
+    }
+
+    /// Create a tool backed by a custom shortcut runner.
+    pub fn with_runner(runner: Arc<dyn ShortcutRunner>) -> Self {
+        Self {
+            runner,
+            cache: Mutex::new(None),
+            ttl: DEFAULT_STATE_TTL,
+        }
+    }
+
+    /// Override how long a state snapshot stays fresh.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Return the cached snapshot and its age, fetching a new one if the
+    /// cache is empty, expired, or `refresh` is set.
+    fn snapshot(&self, refresh: bool) -> Result<(HomeSnapshot, Duration), String> {
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if !refresh
+            && let Some((at, snap)) = cache.as_ref()
+            && at.elapsed() < self.ttl
+        {
+            return Ok((snap.clone(), at.elapsed()));
+        }
+        let raw = self.runner.run(STATE_SHORTCUT, None)?;
+        let snap: HomeSnapshot = serde_json::from_str(raw.trim()).map_err(|e| {
+            format!(
+                "'{}' did not return valid state JSON ({}). Install the bridge shortcut described in the homekit tool docs.",
+                STATE_SHORTCUT, e
+            )
+        })?;
+        *cache = Some((Instant::now(), snap.clone()));
+        Ok((snap, Duration::ZERO))
+    }
+
+    /// Drop the cached snapshot after anything that changes device state.
+    fn invalidate(&self) {
+        *self.cache.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn control(&self, accessory: &str, command: &str, value: &Value) -> Result<String, String> {
+        let input = json!({ "accessory": accessory, "command": command, "value": value });
+        self.runner.run(CONTROL_SHORTCUT, Some(&input.to_string()))
+    }
+
+    fn required<'a>(args: &'a Value, key: &str, action: &str) -> Result<&'a str, ToolError> {
+        args[key]
+            .as_str()
+            .filter(|s| !s.trim().is_empty())
+            .ok_or_else(|| ToolError::InvalidArguments {
+                name: "homekit".to_string(),
+                reason: format!("Missing '{}' parameter for {}", key, action),
+            })
+    }
+
+    fn run_shortcut(&self, name: &str, input: Option<&str>) -> Result<ToolOutput, ToolError> {
+        let stdout =
+            self.runner
+                .run(name, input)
+                .map_err(|message| ToolError::ExecutionFailed {
+                    name: "homekit".to_string(),
+                    message,
+                })?;
+        self.invalidate();
+        if !stdout.is_empty() {
+            return Ok(ToolOutput::text(format!(
+                "Shortcut '{}' output:\n{}",
+                name, stdout
+            )));
+        }
+        Ok(ToolOutput::text(match input {
+            Some(input) => format!("Shortcut '{}' executed with input '{}'.", name, input),
+            None => format!("Shortcut '{}' executed successfully.", name),
+        }))
+    }
+
+    fn list_shortcuts(&self) -> Result<ToolOutput, ToolError> {
+        let all = self
+            .runner
+            .list()
+            .map_err(|message| ToolError::ExecutionFailed {
+                name: "homekit".to_string(),
+                message,
+            })?;
+        // Filter to HomeKit-related shortcuts (heuristic: name contains "home", "light", "scene", etc.)
+        let homekit_keywords = [
+            "home",
+            "light",
+            "scene",
+            "lock",
+            "thermostat",
+            "fan",
+            "blind",
+            "curtain",
+            "door",
+            "garage",
+            "climate",
+            "switch",
+            "plug",
+        ];
+        let relevant: Vec<&str> = all
+            .iter()
+            .map(|s| s.as_str())
+            .filter(|line| {
+                let lower = line.to_lowercase();
+                homekit_keywords.iter().any(|kw| lower.contains(kw))
+            })
+            .collect();
+
+        if relevant.is_empty() {
+            Ok(ToolOutput::text(format!(
+                "No HomeKit-related shortcuts found. All available shortcuts:\n{}",
+                all.iter().take(20).cloned().collect::<Vec<_>>().join("\n")
+            )))
+        } else {
+            Ok(ToolOutput::text(format!(
+                "HomeKit shortcuts ({}):\n{}",
+                relevant.len(),
+                relevant.join("\n")
+            )))
+        }
+    }
+
+    fn list_scenes(&self) -> Result<ToolOutput, ToolError> {
+        let mut scenes: Vec<String> = match self.snapshot(false) {
+            Ok((snap, _)) => snap.scenes,
+            Err(_) => Vec::new(),
+        };
+        if let Ok(shortcuts) = self.runner.list() {
+            for name in shortcuts {
+                if name.to_lowercase().contains("scene")
+                    && name != SCENE_SHORTCUT
+                    && !scenes.iter().any(|s| s.eq_ignore_ascii_case(&name))
+                {
+                    scenes.push(name);
+                }
+            }
+        }
+        if scenes.is_empty() {
+            return Ok(ToolOutput::text(format!(
+                "No scenes found. Install the '{}' shortcut or create shortcuts with 'scene' in the name.",
+                STATE_SHORTCUT
+            )));
+        }
+        Ok(ToolOutput::text(format!(
+            "Scenes ({}):\n{}",
+            scenes.len(),
+            scenes
+                .iter()
+                .map(|s| format!("  - {}", s))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )))
+    }
+
+    fn activate_scene(&self, scene: &str) -> Result<ToolOutput, ToolError> {
+        let has_own_shortcut = self
+            .runner
+            .list()
+            .map(|all| all.iter().any(|s| s == scene))
+            .unwrap_or(false);
+        let result = if has_own_shortcut {
+            self.runner.run(scene, None)
+        } else {
+            self.runner.run(SCENE_SHORTCUT, Some(scene))
+        };
+        self.invalidate();
+        match result {
+            Ok(_) => Ok(ToolOutput::text(format!("Scene '{}' activated.", scene))),
+            Err(e) => Ok(ToolOutput::text(format!(
+                "Scene '{}' could not be activated: {}",
+                scene, e
+            ))),
+        }
+    }
+
+    fn get_state(&self, args: &Value, refresh: bool) -> Result<ToolOutput, ToolError> {
+        let (snap, age) = match self.snapshot(refresh) {
+            Ok(v) => v,
+            Err(e) => return Ok(ToolOutput::text(e)),
+        };
+        let accessory = args["accessory"].as_str().map(|s| s.to_lowercase());
+        let room = args["room"].as_str().map(|s| s.to_lowercase());
+        let matching: Vec<&HomeAccessory> = snap
+            .accessories
+            .iter()
+            .filter(|a| {
+                accessory
+                    .as_deref()
+                    .is_none_or(|n| a.name.to_lowercase().contains(n))
+            })
+            .filter(|a| room.as_deref().is_none_or(|r| a.room.to_lowercase() == r))
+            .collect();
+
+        let freshness = if age.is_zero() {
+            "just refreshed".to_string()
+        } else {
+            format!(
+                "cached {} ago; pass refresh: true for live state",
+                format_age(age)
+            )
+        };
+        if matching.is_empty() {
+            return Ok(ToolOutput::text(format!(
+                "No matching accessories ({}).",
+                freshness
+            )));
+        }
+        let lines: Vec<String> = matching
+            .iter()
+            .map(|a| {
+                format!(
+                    "  {} ({}, {}): {}{}",
+                    a.name,
+                    if a.room.is_empty() {
+                        "no room"
+                    } else {
+                        &a.room
+                    },
+                    if a.category.is_empty() {
+                        "accessory"
+                    } else {
+                        &a.category
+                    },
+                    a.state_text(),
+                    if a.reachable { "" } else { " [unreachable]" }
+                )
+            })
+            .collect();
+        Ok(ToolOutput::text(format!(
+            "Accessory state ({}):\n{}",
+            freshness,
+            lines.join("\n")
+        )))
+    }
+
+    fn control_accessory(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let accessory = Self::required(args, "accessory", "control_accessory")?;
+        let command = Self::required(args, "command", "control_accessory")?;
+
+        // Security devices must be named as such in the call so the safety
+        // guardian sees them and asks for approval.
+        let flagged = is_security_accessory(accessory)
+            || args["category"].as_str().is_some_and(is_security_accessory);
+        if !flagged
+            && let Ok((snap, _)) = self.snapshot(false)
+            && let Some(found) = snap
+                .accessories
+                .iter()
+                .find(|a| a.name.eq_ignore_ascii_case(accessory))
+            && found.is_security()
+        {
+            return Ok(ToolOutput::text(format!(
+                "'{}' is a security device ({}). Repeat the request with \"category\": \"{}\" so it goes through approval.",
+                found.name, found.category, found.category
+            )));
+        }
+
+        let result = self.control(accessory, command, &args["value"]);
+        self.invalidate();
+        match result {
+            Ok(_) => Ok(ToolOutput::text(format!("{}: {} ✓", accessory, command))),
+            Err(e) => Ok(ToolOutput::text(format!(
+                "{}: {} ✗ {}",
+                accessory, command, e
+            ))),
+        }
+    }
+
+    fn room_command(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let room = Self::required(args, "room", "room_command")?;
+        let command = Self::required(args, "command", "room_command")?;
+        let category = args["category"].as_str().unwrap_or("all");
+        let include_security = is_security_accessory(category);
+
+        let (snap, _) = match self.snapshot(false) {
+            Ok(v) => v,
+            Err(e) => return Ok(ToolOutput::text(e)),
+        };
+        let targets: Vec<&HomeAccessory> = snap
+            .accessories
+            .iter()
+            .filter(|a| a.room.eq_ignore_ascii_case(room))
+            .filter(|a| category_matches(category, &a.category))
+            .collect();
+        if targets.is_empty() {
+            return Ok(ToolOutput::text(format!(
+                "No {} accessories found in '{}'.",
+                category, room
+            )));
+        }
+
+        let mut lines = Vec::new();
+        let mut results = Vec::new();
+        let mut attempted = 0usize;
+        let mut succeeded = 0usize;
+        for accessory in targets {
+            let (ok, note) = if accessory.is_security() && !include_security {
+                (
+                    None,
+                    "skipped (security device; target its category to include it)".to_string(),
+                )
+            } else if !accessory.reachable {
+                attempted += 1;
+                (Some(false), "unreachable".to_string())
+            } else {
+                attempted += 1;
+                match self.control(&accessory.name, command, &args["value"]) {
+                    Ok(_) => {
+                        succeeded += 1;
+                        (Some(true), "ok".to_string())
+                    }
+                    Err(e) => (Some(false), e),
+                }
+            };
+            let mark = match ok {
+                Some(true) => "✓",
+                Some(false) => "✗",
+                None => "–",
+            };
+            lines.push(if ok == Some(true) {
+                format!("  {} {}", mark, accessory.name)
+            } else {
+                format!("  {} {} — {}", mark, accessory.name, note)
+            });
+            results.push(json!({
+                "accessory": accessory.name,
+                "success": ok,
+                "note": note,
+            }));
+        }
+        self.invalidate();
+
+        let mut output = ToolOutput::text(format!(
+            "{} {} in {}: {} of {} succeeded\n{}",
+            command,
+            category,
+            room,
+            succeeded,
+            attempted,
+            lines.join("\n")
+        ));
+        output
+            .metadata
+            .insert("room_command".into(), Value::Array(results));
+        Ok(output)
     }
 }
 
@@ -33,7 +522,7 @@ impl Tool for HomeKitTool {
     }
 
     fn description(&self) -> &str {
-        "Control HomeKit smart home accessories via macOS Shortcuts. Actions: list_shortcuts, run_shortcut, run_with_input"
+        "Control HomeKit smart home accessories via macOS Shortcuts. Actions: list_shortcuts, run_shortcut, run_with_input, list_scenes, activate_scene, get_state, refresh_state, control_accessory, room_command. Locks, garage doors, and alarms always require approval."
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -46,7 +535,11 @@ impl Tool for HomeKitTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["list_shortcuts", "run_shortcut", "run_with_input"],
+                    "enum": [
+                        "list_shortcuts", "run_shortcut", "run_with_input",
+                        "list_scenes", "activate_scene", "get_state",
+                        "refresh_state", "control_accessory", "room_command"
+                    ],
                     "description": "The action to perform"
                 },
                 "name": {
@@ -56,6 +549,33 @@ impl Tool for HomeKitTool {
                 "input": {
                     "type": "string",
                     "description": "Input to pass to the shortcut (for run_with_input)"
+                },
+                "scene": {
+                    "type": "string",
+                    "description": "Scene name (for activate_scene)"
+                },
+                "accessory": {
+                    "type": "string",
+                    "description": "Accessory name (for get_state, control_accessory)"
+                },
+                "room": {
+                    "type": "string",
+                    "description": "Room name (for get_state, room_command)"
+                },
+                "category": {
+                    "type": "string",
+                    "description": "Accessory category, e.g. 'lights', 'lock', 'all' (for room_command; required for security devices in control_accessory)"
+                },
+                "command": {
+                    "type": "string",
+                    "description": "Command such as 'on', 'off', 'brightness', 'lock', 'unlock'"
+                },
+                "value": {
+                    "description": "Optional command value (e.g. brightness 40)"
+                },
+                "refresh": {
+                    "type": "boolean",
+                    "description": "Bypass the cached state snapshot (for get_state)"
                 }
             },
             "required": ["action"]
@@ -71,133 +591,25 @@ impl Tool for HomeKitTool {
             })?;
 
         match action {
-            "list_shortcuts" => {
-                let output = Command::new("shortcuts")
-                    .arg("list")
-                    .output()
-                    .map_err(|e| ToolError::ExecutionFailed {
-                        name: "homekit".to_string(),
-                        message: format!("Failed to list shortcuts: {}", e),
-                    })?;
-
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                // Filter to HomeKit-related shortcuts (heuristic: name contains "home", "light", "scene", etc.)
-                let homekit_keywords = [
-                    "home",
-                    "light",
-                    "scene",
-                    "lock",
-                    "thermostat",
-                    "fan",
-                    "blind",
-                    "curtain",
-                    "door",
-                    "garage",
-                    "climate",
-                    "switch",
-                    "plug",
-                ];
-                let relevant: Vec<&str> = stdout
-                    .lines()
-                    .filter(|line| {
-                        let lower = line.to_lowercase();
-                        homekit_keywords.iter().any(|kw| lower.contains(kw))
-                    })
-                    .collect();
-
-                if relevant.is_empty() {
-                    Ok(ToolOutput::text(format!(
-                        "No HomeKit-related shortcuts found. All available shortcuts:\n{}",
-                        stdout.lines().take(20).collect::<Vec<_>>().join("\n")
-                    )))
-                } else {
-                    Ok(ToolOutput::text(format!(
-                        "HomeKit shortcuts ({}):\n{}",
-                        relevant.len(),
-                        relevant.join("\n")
-                    )))
-                }
-            }
+            "list_shortcuts" => self.list_shortcuts(),
             "run_shortcut" => {
-                let name = args["name"]
-                    .as_str()
-                    .ok_or_else(|| ToolError::InvalidArguments {
-                        name: "homekit".to_string(),
-                        reason: "Missing 'name' parameter for run_shortcut".to_string(),
-                    })?;
-
-                let output = Command::new("shortcuts")
-                    .args(["run", name])
-                    .output()
-                    .map_err(|e| ToolError::ExecutionFailed {
-                        name: "homekit".to_string(),
-                        message: format!("Failed to run shortcut '{}': {}", name, e),
-                    })?;
-
-                if output.status.success() {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    if stdout.is_empty() {
-                        Ok(ToolOutput::text(format!(
-                            "Shortcut '{}' executed successfully.",
-                            name
-                        )))
-                    } else {
-                        Ok(ToolOutput::text(format!(
-                            "Shortcut '{}' output:\n{}",
-                            name, stdout
-                        )))
-                    }
-                } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    Err(ToolError::ExecutionFailed {
-                        name: "homekit".to_string(),
-                        message: format!("Shortcut '{}' failed: {}", name, stderr),
-                    })
-                }
+                let name = Self::required(&args, "name", "run_shortcut")?;
+                self.run_shortcut(name, None)
             }
             "run_with_input" => {
-                let name = args["name"]
-                    .as_str()
-                    .ok_or_else(|| ToolError::InvalidArguments {
-                        name: "homekit".to_string(),
-                        reason: "Missing 'name' parameter for run_with_input".to_string(),
-                    })?;
-                let input = args["input"]
-                    .as_str()
-                    .ok_or_else(|| ToolError::InvalidArguments {
-                        name: "homekit".to_string(),
-                        reason: "Missing 'input' parameter for run_with_input".to_string(),
-                    })?;
-
-                let output = Command::new("shortcuts")
-                    .args(["run", name, "--input-type", "text", "--input", input])
-                    .output()
-                    .map_err(|e| ToolError::ExecutionFailed {
-                        name: "homekit".to_string(),
-                        message: format!("Failed to run shortcut '{}' with input: {}", name, e),
-                    })?;
-
-                if output.status.success() {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    if stdout.is_empty() {
-                        Ok(ToolOutput::text(format!(
-                            "Shortcut '{}' executed with input '{}'.",
-                            name, input
-                        )))
-                    } else {
-                        Ok(ToolOutput::text(format!(
-                            "Shortcut '{}' output:\n{}",
-                            name, stdout
-                        )))
-                    }
-                } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    Err(ToolError::ExecutionFailed {
-                        name: "homekit".to_string(),
-                        message: format!("Shortcut '{}' failed: {}", name, stderr),
-                    })
-                }
+                let name = Self::required(&args, "name", "run_with_input")?;
+                let input = Self::required(&args, "input", "run_with_input")?;
+                self.run_shortcut(name, Some(input))
             }
+            "list_scenes" => self.list_scenes(),
+            "activate_scene" => {
+                let scene = Self::required(&args, "scene", "activate_scene")?;
+                self.activate_scene(scene)
+            }
+            "get_state" => self.get_state(&args, args["refresh"].as_bool().unwrap_or(false)),
+            "refresh_state" => self.get_state(&args, true),
+            "control_accessory" => self.control_accessory(&args),
+            "room_command" => self.room_command(&args),
             _ => Err(ToolError::InvalidArguments {
                 name: "homekit".to_string(),
                 reason: format!("Unknown action: {}", action),
@@ -209,6 +621,64 @@ impl Tool for HomeKitTool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct MockRunner {
+        state: Value,
+        state_calls: AtomicUsize,
+        controls: Mutex<Vec<String>>,
+        failing: Vec<String>,
+    }
+
+    impl MockRunner {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                state: json!({
+                    "accessories": [
+                        {"name": "Ceiling Light", "room": "Living Room", "category": "light", "state": "on"},
+                        {"name": "Floor Lamp", "room": "Living Room", "category": "light", "state": "off"},
+                        {"name": "Reading Light", "room": "Living Room", "category": "light", "state": "off", "reachable": false},
+                        {"name": "Patio Door", "room": "Living Room", "category": "lock", "state": "locked"},
+                        {"name": "Bedroom Lamp", "room": "Bedroom", "category": "light", "state": "off"}
+                    ],
+                    "scenes": ["Good Night", "Movie Time"]
+                }),
+                state_calls: AtomicUsize::new(0),
+                controls: Mutex::new(Vec::new()),
+                failing: vec!["Floor Lamp".to_string()],
+            })
+        }
+    }
+
+    impl ShortcutRunner for MockRunner {
+        fn list(&self) -> Result<Vec<String>, String> {
+            Ok(vec![
+                STATE_SHORTCUT.to_string(),
+                CONTROL_SHORTCUT.to_string(),
+                SCENE_SHORTCUT.to_string(),
+                "Porch Scene".to_string(),
+            ])
+        }
+
+        fn run(&self, name: &str, input: Option<&str>) -> Result<String, String> {
+            match name {
+                STATE_SHORTCUT => {
+                    self.state_calls.fetch_add(1, Ordering::SeqCst);
+                    Ok(self.state.to_string())
+                }
+                CONTROL_SHORTCUT => {
+                    let input: Value = serde_json::from_str(input.unwrap_or("{}")).unwrap();
+                    let accessory = input["accessory"].as_str().unwrap_or("").to_string();
+                    if self.failing.contains(&accessory) {
+                        return Err("No response from accessory".to_string());
+                    }
+                    self.controls.lock().unwrap().push(accessory);
+                    Ok(String::new())
+                }
+                _ => Ok(String::new()),
+            }
+        }
+    }
 
     #[test]
     fn test_homekit_schema() {
@@ -238,4 +708,115 @@ mod tests {
         let result = tool.execute(json!({"action": "run_shortcut"})).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_get_state_uses_cache_until_refresh() {
+        let runner = MockRunner::new();
+        let tool = HomeKitTool::with_runner(runner.clone());
+
+        let first = tool
+            .execute(json!({"action": "get_state", "accessory": "ceiling"}))
+            .await
+            .unwrap();
+        assert!(first.content.contains("Ceiling Light"));
+        assert!(first.content.contains("just refreshed"));
+
+        let second = tool
+            .execute(json!({"action": "get_state", "room": "bedroom"}))
+            .await
+            .unwrap();
+        assert!(second.content.contains("cached"));
+        assert!(second.content.contains("Bedroom Lamp"));
+        assert_eq!(runner.state_calls.load(Ordering::SeqCst), 1);
+
+        tool.execute(json!({"action": "refresh_state"}))
+            .await
+            .unwrap();
+        assert_eq!(runner.state_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_room_command_reports_partial_results() {
+        let runner = MockRunner::new();
+        let tool = HomeKitTool::with_runner(runner.clone());
+
+        let output = tool
+            .execute(json!({
+                "action": "room_command",
+                "room": "living room",
+                "category": "lights",
+                "command": "off"
+            }))
+            .await
+            .unwrap();
+        assert!(output.content.contains("1 of 3 succeeded"));
+        assert!(output.content.contains("✓ Ceiling Light"));
+        assert!(output.content.contains("Floor Lamp — No response"));
+        assert!(output.content.contains("Reading Light — unreachable"));
+        assert_eq!(output.metadata["room_command"].as_array().unwrap().len(), 3);
+        assert_eq!(*runner.controls.lock().unwrap(), vec!["Ceiling Light"]);
+    }
+
+    #[tokio::test]
+    async fn test_room_command_all_skips_security_devices() {
+        let runner = MockRunner::new();
+        let tool = HomeKitTool::with_runner(runner.clone());
+
+        let output = tool
+            .execute(json!({"action": "room_command", "room": "Living Room", "command": "off"}))
+            .await
+            .unwrap();
+        assert!(output.content.contains("Patio Door — skipped"));
+        assert!(
+            !runner
+                .controls
+                .lock()
+                .unwrap()
+                .contains(&"Patio Door".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_control_security_accessory_requires_category() {
+        let runner = MockRunner::new();
+        let tool = HomeKitTool::with_runner(runner.clone());
+
+        let refused = tool
+            .execute(json!({"action": "control_accessory", "accessory": "Patio Door", "command": "unlock"}))
+            .await
+            .unwrap();
+        assert!(refused.content.contains("security device"));
+        assert!(runner.controls.lock().unwrap().is_empty());
+
+        let done = tool
+            .execute(json!({
+                "action": "control_accessory",
+                "accessory": "Patio Door",
+                "category": "lock",
+                "command": "unlock"
+            }))
+            .await
+            .unwrap();
+        assert!(done.content.contains('✓'));
+    }
+
+    #[tokio::test]
+    async fn test_scenes_listed_and_activated() {
+        let runner = MockRunner::new();
+        let tool = HomeKitTool::with_runner(runner);
+
+        let scenes = tool
+            .execute(json!({"action": "list_scenes"}))
+            .await
+            .unwrap();
+        assert!(scenes.content.contains("Good Night"));
+        assert!(scenes.content.contains("Porch Scene"));
+        assert!(!scenes.content.contains(SCENE_SHORTCUT));
+
+        let activated = tool
+            .execute(json!({"action": "activate_scene", "scene": "Movie Time"}))
+            .await
+            .unwrap();
+        assert!(activated.content.contains("activated"));
+    }
 }
@@ -0,0 +1,1238 @@
+//! GraphQL documents — parsing, validation against a [`Schema`], and the
+//! depth and node-count estimates used as cost guards.
+//!
+//! Validation covers what a model most often gets wrong: unknown fields
+//! and arguments, missing or superfluous selection sets, and undeclared or
+//! missing variables. Errors name the path and list what is valid there.
+
+use rustant_core::config::GraphqlConfig;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+use super::schema::{InputValue, Schema, TypeDef};
+
+/// Arguments read as a list field's page size when estimating nodes.
+const PAGE_SIZE_ARGS: &[&str] = &[
+    "first", "last", "limit", "take", "top", "pageSize", "perPage",
+];
+
+/// Why a query was not sent.
+#[derive(Debug, Clone, PartialEq, Serialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GraphqlError {
+    #[error("syntax error at line {line}, column {column}: {message}")]
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[error("field '{field}' does not exist on type '{type_name}' at {path}; valid fields: {}", .valid.join(", "))]
+    UnknownField {
+        path: String,
+        type_name: String,
+        field: String,
+        valid: Vec<String>,
+    },
+
+    #[error(
+        "argument '{argument}' is not accepted by {path}; valid arguments: {}",
+        list_or_none(.valid)
+    )]
+    UnknownArgument {
+        path: String,
+        argument: String,
+        valid: Vec<String>,
+    },
+
+    #[error("{path} requires argument '{argument}: {type_name}'")]
+    MissingArgument {
+        path: String,
+        argument: String,
+        type_name: String,
+    },
+
+    #[error("{path} returns '{type_name}', so it needs a selection of its fields: {}", .valid.join(", "))]
+    MissingSelection {
+        path: String,
+        type_name: String,
+        valid: Vec<String>,
+    },
+
+    #[error("{path} returns '{type_name}', which has no fields to select")]
+    LeafSelection { path: String, type_name: String },
+
+    #[error("type '{type_name}' at {path} is not an object, interface or union in the schema")]
+    UnknownType { path: String, type_name: String },
+
+    #[error("fragment '{name}' is not defined")]
+    UnknownFragment { name: String },
+
+    #[error("variable '${name}' is not declared by the operation")]
+    UndeclaredVariable { name: String },
+
+    #[error("required variable '${name}' ({type_name}) was not provided")]
+    MissingVariable { name: String, type_name: String },
+
+    #[error("the document has several operations; pass operation_name (one of: {})", .names.join(", "))]
+    AmbiguousOperation { names: Vec<String> },
+
+    #[error("operation '{name}' is not in the document")]
+    UnknownOperation { name: String },
+
+    #[error("the document has no operation")]
+    NoOperation,
+
+    #[error("the schema has no {operation} type")]
+    NoRootType { operation: String },
+
+    #[error("subscriptions are not supported; use a query and poll instead")]
+    Subscription,
+
+    #[error("query depth {depth} exceeds the limit of {limit} ([tools.graphql] max_depth)")]
+    TooDeep { depth: usize, limit: usize },
+
+    #[error(
+        "query may return about {nodes} nodes, over the limit of {limit} ([tools.graphql] max_nodes); \
+         pass smaller first/last arguments or select fewer list fields"
+    )]
+    TooManyNodes { nodes: u64, limit: u64 },
+}
+
+fn list_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "none".into()
+    } else {
+        items.join(", ")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Documents
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+impl OperationKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Query => "query",
+            Self::Mutation => "mutation",
+            Self::Subscription => "subscription",
+        }
+    }
+}
+
+/// An executable GraphQL document.
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    pub operations: Vec<Operation>,
+    pub fragments: HashMap<String, Fragment>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub kind: OperationKind,
+    pub name: Option<String>,
+    pub variables: Vec<VariableDef>,
+    pub selections: Vec<Selection>,
+}
+
+#[derive(Debug, Clone)]
+pub struct VariableDef {
+    pub name: String,
+    /// The declared type as written, e.g. `[ID!]!`.
+    pub type_name: String,
+    pub non_null: bool,
+    pub has_default: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Fragment {
+    pub type_condition: String,
+    pub selections: Vec<Selection>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Selection {
+    Field(Field),
+    FragmentSpread {
+        name: String,
+        directives: Vec<Directive>,
+    },
+    InlineFragment {
+        type_condition: Option<String>,
+        directives: Vec<Directive>,
+        selections: Vec<Selection>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub alias: Option<String>,
+    pub name: String,
+    pub arguments: Vec<(String, GqlValue)>,
+    pub directives: Vec<Directive>,
+    pub selections: Vec<Selection>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Directive {
+    pub name: String,
+    pub arguments: Vec<(String, GqlValue)>,
+}
+
+/// An argument value as written in the document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GqlValue {
+    Variable(String),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Boolean(bool),
+    Null,
+    Enum(String),
+    List(Vec<GqlValue>),
+    Object(Vec<(String, GqlValue)>),
+}
+
+impl GqlValue {
+    fn variables<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Self::Variable(name) => out.push(name),
+            Self::List(items) => items.iter().for_each(|v| v.variables(out)),
+            Self::Object(fields) => fields.iter().for_each(|(_, v)| v.variables(out)),
+            _ => {}
+        }
+    }
+}
+
+impl Document {
+    /// The operation to run: the one named `name`, or the only one.
+    pub fn operation(&self, name: Option<&str>) -> Result<&Operation, GraphqlError> {
+        match name {
+            Some(name) => self
+                .operations
+                .iter()
+                .find(|op| op.name.as_deref() == Some(name))
+                .ok_or_else(|| GraphqlError::UnknownOperation {
+                    name: name.to_string(),
+                }),
+            None => match self.operations.as_slice() {
+                [] => Err(GraphqlError::NoOperation),
+                [op] => Ok(op),
+                ops => Err(GraphqlError::AmbiguousOperation {
+                    names: ops
+                        .iter()
+                        .map(|op| op.name.clone().unwrap_or_else(|| "(anonymous)".into()))
+                        .collect(),
+                }),
+            },
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Lexer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Punct(char),
+    Spread,
+    Name(String),
+    Int(String),
+    Float(String),
+    Str(String),
+    End,
+}
+
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Lexer {
+    fn peek(&self, ahead: usize) -> Option<char> {
+        self.chars.get(self.pos + ahead).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, message: impl Into<String>) -> GraphqlError {
+        GraphqlError::Syntax {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    fn tokenize(source: &str) -> Result<Vec<Spanned>, GraphqlError> {
+        let mut lexer = Self {
+            chars: source.chars().collect(),
+            pos: 0,
+            line: 1,
+            column: 1,
+        };
+        let mut tokens = Vec::new();
+        loop {
+            let (line, column) = (lexer.line, lexer.column);
+            let Some(c) = lexer.peek(0) else {
+                tokens.push(Spanned {
+                    token: Token::End,
+                    line,
+                    column,
+                });
+                return Ok(tokens);
+            };
+            let token = match c {
+                c if c.is_whitespace() || c == ',' || c == '\u{feff}' => {
+                    lexer.bump();
+                    continue;
+                }
+                '#' => {
+                    while lexer.peek(0).is_some_and(|c| c != '\n') {
+                        lexer.bump();
+                    }
+                    continue;
+                }
+                '.' => {
+                    if lexer.peek(1) != Some('.') || lexer.peek(2) != Some('.') {
+                        return Err(lexer.error("expected '...'"));
+                    }
+                    (0..3).for_each(|_| {
+                        lexer.bump();
+                    });
+                    Token::Spread
+                }
+                '!' | '$' | '&' | '(' | ')' | ':' | '=' | '@' | '[' | ']' | '{' | '|' | '}' => {
+                    lexer.bump();
+                    Token::Punct(c)
+                }
+                '"' => lexer.string()?,
+                c if c == '_' || c.is_ascii_alphabetic() => {
+                    let mut name = String::new();
+                    while let Some(c) = lexer
+                        .peek(0)
+                        .filter(|c| *c == '_' || c.is_ascii_alphanumeric())
+                    {
+                        name.push(c);
+                        lexer.bump();
+                    }
+                    Token::Name(name)
+                }
+                c if c == '-' || c.is_ascii_digit() => lexer.number()?,
+                c => return Err(lexer.error(format!("unexpected character '{}'", c))),
+            };
+            tokens.push(Spanned {
+                token,
+                line,
+                column,
+            });
+        }
+    }
+
+    fn number(&mut self) -> Result<Token, GraphqlError> {
+        let mut text = String::new();
+        let mut float = false;
+        while let Some(c) = self.peek(0) {
+            match c {
+                '0'..='9' | '-' | '+' => {}
+                '.' | 'e' | 'E' => float = true,
+                _ => break,
+            }
+            text.push(c);
+            self.bump();
+        }
+        if float {
+            text.parse::<f64>()
+                .map(|_| Token::Float(text.clone()))
+                .map_err(|_| self.error(format!("invalid number '{}'", text)))
+        } else {
+            text.parse::<i64>()
+                .map(|_| Token::Int(text.clone()))
+                .map_err(|_| self.error(format!("invalid number '{}'", text)))
+        }
+    }
+
+    fn string(&mut self) -> Result<Token, GraphqlError> {
+        let mut value = String::new();
+        if self.peek(1) == Some('"') && self.peek(2) == Some('"') {
+            (0..3).for_each(|_| {
+                self.bump();
+            });
+            loop {
+                match self.peek(0) {
+                    None => return Err(self.error("unterminated block string")),
+                    Some('"') if self.peek(1) == Some('"') && self.peek(2) == Some('"') => {
+                        (0..3).for_each(|_| {
+                            self.bump();
+                        });
+                        return Ok(Token::Str(value));
+                    }
+                    Some('\\')
+                        if self.peek(1) == Some('"')
+                            && self.peek(2) == Some('"')
+                            && self.peek(3) == Some('"') =>
+                    {
+                        (0..4).for_each(|_| {
+                            self.bump();
+                        });
+                        value.push_str("\"\"\"");
+                    }
+                    Some(c) => {
+                        self.bump();
+                        value.push(c);
+                    }
+                }
+            }
+        }
+
+        self.bump();
+        loop {
+            match self.bump() {
+                None | Some('\n') => return Err(self.error("unterminated string")),
+                Some('"') => return Ok(Token::Str(value)),
+                Some('\\') => {
+                    let escaped = match self.bump() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('u') => {
+                            let hex: String = (0..4).filter_map(|_| self.bump()).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("invalid unicode escape"))?
+                        }
+                        Some(c @ ('"' | '\\' | '/')) => c,
+                        _ => return Err(self.error("invalid escape sequence")),
+                    };
+                    value.push(escaped);
+                }
+                Some(c) => value.push(c),
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+/// Parse an executable document (operations and fragments).
+pub fn parse(source: &str) -> Result<Document, GraphqlError> {
+    let mut parser = Parser {
+        tokens: Lexer::tokenize(source)?,
+        pos: 0,
+    };
+    let mut document = Document::default();
+    while parser.peek() != &Token::End {
+        match parser.peek().clone() {
+            Token::Punct('{') => document.operations.push(Operation {
+                kind: OperationKind::Query,
+                name: None,
+                variables: Vec::new(),
+                selections: parser.selection_set()?,
+            }),
+            Token::Name(word) if word == "fragment" => {
+                parser.next();
+                let name = parser.name()?;
+                parser.keyword("on")?;
+                let type_condition = parser.name()?;
+                parser.directives()?;
+                let selections = parser.selection_set()?;
+                document.fragments.insert(
+                    name,
+                    Fragment {
+                        type_condition,
+                        selections,
+                    },
+                );
+            }
+            Token::Name(word) if matches!(word.as_str(), "query" | "mutation" | "subscription") => {
+                document.operations.push(parser.operation()?);
+            }
+            _ => return Err(parser.error("expected an operation or fragment definition")),
+        }
+    }
+    Ok(document)
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos.min(self.tokens.len() - 1)].token
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.peek().clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: &str) -> GraphqlError {
+        let at = &self.tokens[self.pos.min(self.tokens.len() - 1)];
+        let found = match &at.token {
+            Token::Punct(c) => format!("'{}'", c),
+            Token::Spread => "'...'".into(),
+            Token::Name(n) => format!("'{}'", n),
+            Token::Int(n) | Token::Float(n) => n.clone(),
+            Token::Str(_) => "a string".into(),
+            Token::End => "end of document".into(),
+        };
+        GraphqlError::Syntax {
+            line: at.line,
+            column: at.column,
+            message: format!("{}, found {}", message, found),
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == &Token::Punct(c) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), GraphqlError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn name(&mut self) -> Result<String, GraphqlError> {
+        match self.peek() {
+            Token::Name(name) => {
+                let name = name.clone();
+                self.next();
+                Ok(name)
+            }
+            _ => Err(self.error("expected a name")),
+        }
+    }
+
+    fn keyword(&mut self, word: &str) -> Result<(), GraphqlError> {
+        match self.peek() {
+            Token::Name(name) if name == word => {
+                self.next();
+                Ok(())
+            }
+            _ => Err(self.error(&format!("expected '{}'", word))),
+        }
+    }
+
+    fn operation(&mut self) -> Result<Operation, GraphqlError> {
+        let kind = match self.name()?.as_str() {
+            "mutation" => OperationKind::Mutation,
+            "subscription" => OperationKind::Subscription,
+            _ => OperationKind::Query,
+        };
+        let name = match self.peek() {
+            Token::Name(_) => Some(self.name()?),
+            _ => None,
+        };
+        let mut variables = Vec::new();
+        if self.eat('(') {
+            while !self.eat(')') {
+                self.expect('$')?;
+                let name = self.name()?;
+                self.expect(':')?;
+                let (type_name, non_null) = self.type_ref()?;
+                let has_default = self.eat('=');
+                if has_default {
+                    self.value()?;
+                }
+                self.directives()?;
+                variables.push(VariableDef {
+                    name,
+                    type_name,
+                    non_null,
+                    has_default,
+                });
+            }
+        }
+        self.directives()?;
+        Ok(Operation {
+            kind,
+            name,
+            variables,
+            selections: self.selection_set()?,
+        })
+    }
+
+    fn type_ref(&mut self) -> Result<(String, bool), GraphqlError> {
+        let mut display = if self.eat('[') {
+            let (inner, _) = self.type_ref()?;
+            self.expect(']')?;
+            format!("[{}]", inner)
+        } else {
+            self.name()?
+        };
+        let non_null = self.eat('!');
+        if non_null {
+            display.push('!');
+        }
+        Ok((display, non_null))
+    }
+
+    fn selection_set(&mut self) -> Result<Vec<Selection>, GraphqlError> {
+        self.expect('{')?;
+        let mut selections = Vec::new();
+        while !self.eat('}') {
+            selections.push(self.selection()?);
+        }
+        if selections.is_empty() {
+            return Err(self.error("a selection set must select at least one field"));
+        }
+        Ok(selections)
+    }
+
+    fn selection(&mut self) -> Result<Selection, GraphqlError> {
+        if self.peek() == &Token::Spread {
+            self.next();
+            return match self.peek().clone() {
+                Token::Name(name) if name != "on" => {
+                    self.next();
+                    Ok(Selection::FragmentSpread {
+                        name,
+                        directives: self.directives()?,
+                    })
+                }
+                _ => {
+                    let type_condition = match self.peek() {
+                        Token::Name(_) => {
+                            self.next();
+                            Some(self.name()?)
+                        }
+                        _ => None,
+                    };
+                    Ok(Selection::InlineFragment {
+                        type_condition,
+                        directives: self.directives()?,
+                        selections: self.selection_set()?,
+                    })
+                }
+            };
+        }
+
+        let mut name = self.name()?;
+        let mut alias = None;
+        if self.eat(':') {
+            alias = Some(name);
+            name = self.name()?;
+        }
+        let arguments = self.arguments()?;
+        let directives = self.directives()?;
+        let selections = if self.peek() == &Token::Punct('{') {
+            self.selection_set()?
+        } else {
+            Vec::new()
+        };
+        Ok(Selection::Field(Field {
+            alias,
+            name,
+            arguments,
+            directives,
+            selections,
+        }))
+    }
+
+    fn arguments(&mut self) -> Result<Vec<(String, GqlValue)>, GraphqlError> {
+        let mut arguments = Vec::new();
+        if self.eat('(') {
+            while !self.eat(')') {
+                let name = self.name()?;
+                self.expect(':')?;
+                arguments.push((name, self.value()?));
+            }
+        }
+        Ok(arguments)
+    }
+
+    fn directives(&mut self) -> Result<Vec<Directive>, GraphqlError> {
+        let mut directives = Vec::new();
+        while self.eat('@') {
+            directives.push(Directive {
+                name: self.name()?,
+                arguments: self.arguments()?,
+            });
+        }
+        Ok(directives)
+    }
+
+    fn value(&mut self) -> Result<GqlValue, GraphqlError> {
+        let starts_value = match self.peek() {
+            Token::Punct(c) => matches!(c, '$' | '[' | '{'),
+            Token::Spread | Token::End => false,
+            _ => true,
+        };
+        if !starts_value {
+            return Err(self.error("expected a value"));
+        }
+        let value = match self.next() {
+            Token::Punct('$') => GqlValue::Variable(self.name()?),
+            Token::Int(n) => GqlValue::Int(n.parse().unwrap_or_default()),
+            Token::Float(n) => GqlValue::Float(n.parse().unwrap_or_default()),
+            Token::Str(s) => GqlValue::String(s),
+            Token::Name(name) => match name.as_str() {
+                "true" => GqlValue::Boolean(true),
+                "false" => GqlValue::Boolean(false),
+                "null" => GqlValue::Null,
+                _ => GqlValue::Enum(name),
+            },
+            Token::Punct('[') => {
+                let mut items = Vec::new();
+                while !self.eat(']') {
+                    items.push(self.value()?);
+                }
+                GqlValue::List(items)
+            }
+            Token::Punct('{') => {
+                let mut fields = Vec::new();
+                while !self.eat('}') {
+                    let name = self.name()?;
+                    self.expect(':')?;
+                    fields.push((name, self.value()?));
+                }
+                GqlValue::Object(fields)
+            }
+            _ => return Err(self.error("expected a value")),
+        };
+        Ok(value)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation and cost
+// ---------------------------------------------------------------------------
+
+/// What a validated operation is expected to cost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryCost {
+    pub operation: Option<String>,
+    pub kind: OperationKind,
+    /// Deepest field nesting; root fields are at depth 1.
+    pub depth: usize,
+    /// Estimated nodes in the response.
+    pub nodes: u64,
+}
+
+/// Validate the chosen operation of `document` against `schema` and
+/// `variables`, and estimate its cost. Fails if the cost is over the
+/// limits in `config`.
+pub fn check(
+    schema: &Schema,
+    document: &Document,
+    operation_name: Option<&str>,
+    variables: &Map<String, Value>,
+    config: &GraphqlConfig,
+) -> Result<QueryCost, GraphqlError> {
+    let operation = document.operation(operation_name)?;
+    let root = match operation.kind {
+        OperationKind::Subscription => return Err(GraphqlError::Subscription),
+        OperationKind::Query => &schema.query_type,
+        OperationKind::Mutation => &schema.mutation_type,
+    };
+    let root = root
+        .as_deref()
+        .and_then(|name| schema.type_def(name))
+        .ok_or_else(|| GraphqlError::NoRootType {
+            operation: operation.kind.as_str().to_string(),
+        })?;
+
+    for var in &operation.variables {
+        if var.non_null && !var.has_default && variables.get(&var.name).is_none_or(Value::is_null) {
+            return Err(GraphqlError::MissingVariable {
+                name: var.name.clone(),
+                type_name: var.type_name.clone(),
+            });
+        }
+    }
+
+    let mut walker = Walker {
+        schema,
+        document,
+        declared: operation
+            .variables
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect(),
+        variables,
+        default_list_size: config.default_list_size.max(1),
+        depth: 0,
+        nodes: 0,
+    };
+    walker.walk(
+        root,
+        &operation.selections,
+        &root.name,
+        Scope {
+            depth: 1,
+            multiplier: 1,
+            page_size: None,
+        },
+        &mut Vec::new(),
+    )?;
+
+    if walker.depth > config.max_depth {
+        return Err(GraphqlError::TooDeep {
+            depth: walker.depth,
+            limit: config.max_depth,
+        });
+    }
+    if walker.nodes > config.max_nodes {
+        return Err(GraphqlError::TooManyNodes {
+            nodes: walker.nodes,
+            limit: config.max_nodes,
+        });
+    }
+    Ok(QueryCost {
+        operation: operation.name.clone(),
+        kind: operation.kind,
+        depth: walker.depth,
+        nodes: walker.nodes,
+    })
+}
+
+/// Where in the query a selection set sits.
+#[derive(Clone, Copy)]
+struct Scope {
+    depth: usize,
+    /// Items expected for each field in the selection set.
+    multiplier: u64,
+    /// Page size given to the enclosing connection field, which applies
+    /// to its list children (`edges`, `nodes`).
+    page_size: Option<u64>,
+}
+
+struct Walker<'a> {
+    schema: &'a Schema,
+    document: &'a Document,
+    declared: HashSet<&'a str>,
+    variables: &'a Map<String, Value>,
+    default_list_size: u64,
+    depth: usize,
+    nodes: u64,
+}
+
+impl<'a> Walker<'a> {
+    fn walk(
+        &mut self,
+        parent: &'a TypeDef,
+        selections: &'a [Selection],
+        path: &str,
+        scope: Scope,
+        fragments: &mut Vec<&'a str>,
+    ) -> Result<(), GraphqlError> {
+        for selection in selections {
+            match selection {
+                Selection::Field(field) => self.field(parent, field, path, scope, fragments)?,
+                Selection::FragmentSpread { name, directives } => {
+                    self.check_directives(directives)?;
+                    let fragment = self
+                        .document
+                        .fragments
+                        .get(name)
+                        .ok_or_else(|| GraphqlError::UnknownFragment { name: name.clone() })?;
+                    if fragments.contains(&name.as_str()) {
+                        continue;
+                    }
+                    let ty = self.composite(&fragment.type_condition, path)?;
+                    fragments.push(name);
+                    self.walk(ty, &fragment.selections, path, scope, fragments)?;
+                    fragments.pop();
+                }
+                Selection::InlineFragment {
+                    type_condition,
+                    directives,
+                    selections,
+                } => {
+                    self.check_directives(directives)?;
+                    let ty = match type_condition {
+                        Some(name) => self.composite(name, path)?,
+                        None => parent,
+                    };
+                    self.walk(ty, selections, path, scope, fragments)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn field(
+        &mut self,
+        parent: &'a TypeDef,
+        field: &'a Field,
+        path: &str,
+        scope: Scope,
+        fragments: &mut Vec<&'a str>,
+    ) -> Result<(), GraphqlError> {
+        self.check_directives(&field.directives)?;
+        self.depth = self.depth.max(scope.depth);
+        if field.name == "__typename" {
+            self.nodes = self.nodes.saturating_add(scope.multiplier);
+            return Ok(());
+        }
+        let path = format!("{}.{}", path, field.name);
+        if matches!(field.name.as_str(), "__schema" | "__type")
+            && self.schema.query_type.as_deref() == Some(parent.name.as_str())
+        {
+            // Introspection is answered from the schema, not the data.
+            self.nodes = self.nodes.saturating_add(1);
+            return Ok(());
+        }
+        let def = parent
+            .field(&field.name)
+            .ok_or_else(|| GraphqlError::UnknownField {
+                path: path.clone(),
+                type_name: parent.name.clone(),
+                field: field.name.clone(),
+                valid: parent.selectable(),
+            })?;
+        self.check_arguments(&path, &def.args, &field.arguments)?;
+
+        let target = self.schema.type_def(&def.ty.name);
+        let Some(target) = target.filter(|t| t.is_composite()) else {
+            if !field.selections.is_empty() {
+                return Err(GraphqlError::LeafSelection {
+                    path,
+                    type_name: def.ty.display.clone(),
+                });
+            }
+            self.nodes = self.nodes.saturating_add(scope.multiplier);
+            return Ok(());
+        };
+        if field.selections.is_empty() {
+            return Err(GraphqlError::MissingSelection {
+                path,
+                type_name: def.ty.display.clone(),
+                valid: target.selectable(),
+            });
+        }
+
+        let page_size = self.page_size(&field.arguments);
+        let multiplier = if def.ty.list {
+            let size = page_size
+                .or(scope.page_size)
+                .unwrap_or(self.default_list_size);
+            scope.multiplier.saturating_mul(size)
+        } else {
+            scope.multiplier
+        };
+        self.nodes = self.nodes.saturating_add(multiplier);
+        let child = Scope {
+            depth: scope.depth + 1,
+            multiplier,
+            // A page size on a connection object carries to its lists.
+            page_size: if def.ty.list { None } else { page_size },
+        };
+        self.walk(target, &field.selections, &path, child, fragments)
+    }
+
+    fn composite(&self, name: &str, path: &str) -> Result<&'a TypeDef, GraphqlError> {
+        self.schema
+            .type_def(name)
+            .filter(|t| t.is_composite())
+            .ok_or_else(|| GraphqlError::UnknownType {
+                path: path.to_string(),
+                type_name: name.to_string(),
+            })
+    }
+
+    fn check_arguments(
+        &self,
+        path: &str,
+        defs: &[InputValue],
+        given: &[(String, GqlValue)],
+    ) -> Result<(), GraphqlError> {
+        for (name, value) in given {
+            if !defs.iter().any(|d| &d.name == name) {
+                return Err(GraphqlError::UnknownArgument {
+                    path: path.to_string(),
+                    argument: name.clone(),
+                    valid: defs
+                        .iter()
+                        .map(|d| format!("{}: {}", d.name, d.ty.display))
+                        .collect(),
+                });
+            }
+            self.check_variables(value)?;
+        }
+        if let Some(missing) = defs
+            .iter()
+            .find(|d| d.is_required() && !given.iter().any(|(name, _)| name == &d.name))
+        {
+            return Err(GraphqlError::MissingArgument {
+                path: path.to_string(),
+                argument: missing.name.clone(),
+                type_name: missing.ty.display.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn check_directives(&self, directives: &[Directive]) -> Result<(), GraphqlError> {
+        directives
+            .iter()
+            .flat_map(|d| &d.arguments)
+            .try_for_each(|(_, value)| self.check_variables(value))
+    }
+
+    fn check_variables(&self, value: &GqlValue) -> Result<(), GraphqlError> {
+        let mut used = Vec::new();
+        value.variables(&mut used);
+        match used.into_iter().find(|v| !self.declared.contains(v)) {
+            Some(name) => Err(GraphqlError::UndeclaredVariable {
+                name: name.to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// The page size requested by a field's arguments, from a literal or
+    /// a provided variable.
+    fn page_size(&self, arguments: &[(String, GqlValue)]) -> Option<u64> {
+        arguments
+            .iter()
+            .filter(|(name, _)| PAGE_SIZE_ARGS.contains(&name.as_str()))
+            .find_map(|(_, value)| match value {
+                GqlValue::Int(n) => u64::try_from(*n).ok(),
+                GqlValue::Variable(var) => self.variables.get(var).and_then(Value::as_u64),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::schema::tests::blog_schema;
+    use super::*;
+    use serde_json::json;
+
+    fn run(query: &str, variables: Value) -> Result<QueryCost, GraphqlError> {
+        let document = parse(query)?;
+        let variables = variables.as_object().cloned().unwrap_or_default();
+        check(
+            &blog_schema(),
+            &document,
+            None,
+            &variables,
+            &GraphqlConfig::default(),
+        )
+    }
+
+    #[test]
+    fn test_parse_document() {
+        let document = parse(
+            r#"
+            # Fetch a user
+            query User($id: ID!, $n: Int = 5) @cached {
+              me: user(id: $id) {
+                ...UserFields
+                posts(first: $n) { title status }
+              }
+            }
+            fragment UserFields on User { id name @include(if: true) }
+            "#,
+        )
+        .unwrap();
+        let op = document.operation(None).unwrap();
+        assert_eq!(op.name.as_deref(), Some("User"));
+        assert_eq!(op.variables[0].type_name, "ID!");
+        assert!(op.variables[1].has_default);
+        let Selection::Field(field) = &op.selections[0] else {
+            panic!("expected a field");
+        };
+        assert_eq!(field.alias.as_deref(), Some("me"));
+        assert_eq!(field.name, "user");
+        assert_eq!(
+            field.arguments[0],
+            ("id".to_string(), GqlValue::Variable("id".into()))
+        );
+        assert_eq!(document.fragments["UserFields"].type_condition, "User");
+
+        let value = parse(r#"{ search(term: "a \"quoted\" é") { __typename } }"#).unwrap();
+        let Selection::Field(field) = &value.operations[0].selections[0] else {
+            panic!("expected a field");
+        };
+        assert_eq!(
+            field.arguments[0].1,
+            GqlValue::String("a \"quoted\" é".into())
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors_have_positions() {
+        let err = parse("query {\n  user(id: 1 {\n}").unwrap_err();
+        assert!(matches!(err, GraphqlError::Syntax { line: 2, .. }), "{err}");
+        assert!(parse("{ }").is_err());
+        assert!(parse(r#"{ user(id: "open) { id } }"#).is_err());
+    }
+
+    #[test]
+    fn test_unknown_field_lists_valid_fields() {
+        let err = run(r#"{ user(id: "1") { id fullName } }"#, json!({})).unwrap_err();
+        let GraphqlError::UnknownField {
+            path,
+            type_name,
+            valid,
+            ..
+        } = &err
+        else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(path, "Query.user.fullName");
+        assert_eq!(type_name, "User");
+        assert!(valid.contains(&"name".to_string()));
+        assert!(err.to_string().contains("valid fields: id, name, email"));
+
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["kind"], "unknown_field");
+        assert_eq!(json["field"], "fullName");
+    }
+
+    #[test]
+    fn test_selection_and_argument_errors() {
+        assert!(matches!(
+            run(r#"{ user(id: "1") }"#, json!({})),
+            Err(GraphqlError::MissingSelection { .. })
+        ));
+        assert!(matches!(
+            run(r#"{ user(id: "1") { name { first } } }"#, json!({})),
+            Err(GraphqlError::LeafSelection { .. })
+        ));
+        assert!(matches!(
+            run(r#"{ user(login: "x") { id } }"#, json!({})),
+            Err(GraphqlError::UnknownArgument { ref valid, .. }) if valid == &["id: ID!".to_string()]
+        ));
+        assert!(matches!(
+            run("{ user { id } }", json!({})),
+            Err(GraphqlError::MissingArgument { ref argument, .. }) if argument == "id"
+        ));
+        assert!(matches!(
+            run(
+                r#"{ search(term: "x") { ... on Comment { id } } }"#,
+                json!({})
+            ),
+            Err(GraphqlError::UnknownType { .. })
+        ));
+        assert!(run(
+            r#"{ search(term: "x") { __typename ... on User { name } ... on Post { title } } }"#,
+            json!({})
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_variables_are_checked() {
+        let query = "query($id: ID!) { user(id: $id) { name } }";
+        assert!(matches!(
+            run(query, json!({})),
+            Err(GraphqlError::MissingVariable { ref name, .. }) if name == "id"
+        ));
+        assert!(run(query, json!({"id": "1"})).is_ok());
+        assert!(matches!(
+            run("{ user(id: $id) { name } }", json!({"id": "1"})),
+            Err(GraphqlError::UndeclaredVariable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_operation_selection() {
+        let document = parse("query A { users { id } } mutation B { createPost(title: \"x\") { id } } subscription C { users { id } }").unwrap();
+        let schema = blog_schema();
+        let config = GraphqlConfig::default();
+        let vars = Map::new();
+        assert!(matches!(
+            check(&schema, &document, None, &vars, &config),
+            Err(GraphqlError::AmbiguousOperation { ref names }) if names.len() == 3
+        ));
+        let cost = check(&schema, &document, Some("B"), &vars, &config).unwrap();
+        assert_eq!(cost.kind, OperationKind::Mutation);
+        assert_eq!(
+            check(&schema, &document, Some("C"), &vars, &config),
+            Err(GraphqlError::Subscription)
+        );
+    }
+
+    #[test]
+    fn test_cost_estimate_uses_page_sizes() {
+        // 50 users, each with 10 posts (the default list size) of 2 fields.
+        let cost = run("{ users(first: 50) { id posts { id title } } }", json!({})).unwrap();
+        assert_eq!(cost.depth, 3);
+        assert_eq!(cost.nodes, 50 + 50 + 500 + 500 * 2);
+
+        // A connection's page size applies to its `nodes` list.
+        let cost = run(
+            "query($n: Int) { users(first: 1) { followers(first: $n) { nodes { id } } } }",
+            json!({"n": 3}),
+        )
+        .unwrap();
+        assert_eq!(cost.nodes, 1 + 1 + 3 + 3);
+        assert_eq!(cost.depth, 4);
+    }
+
+    #[test]
+    fn test_cost_guards() {
+        let err = run(
+            "{ users(first: 100) { posts(first: 100) { author { posts(first: 100) { id } } } } }",
+            json!({}),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            GraphqlError::TooManyNodes { limit: 10_000, .. }
+        ));
+
+        let deep = format!(
+            "{{ user(id: 1) {{ {} id {} }} }}",
+            "posts(first: 1) { author { ".repeat(6),
+            "} } ".repeat(6)
+        );
+        assert!(matches!(
+            run(&deep, json!({})),
+            Err(GraphqlError::TooDeep {
+                depth: 14,
+                limit: 12
+            })
+        ));
+    }
+}
@@ -0,0 +1,1030 @@
+//! HTTP API tool — make HTTP requests (GET, POST, PUT, DELETE) and GraphQL
+//! queries.
+//!
+//! GraphQL endpoints are introspected once and their schema is cached for
+//! the session. `graphql_query` validates each query against the schema and
+//! estimates its depth and node count before sending it, so a malformed or
+//! pathological query fails with the valid alternatives instead of reaching
+//! the server. `schema_search` finds types and fields by keyword, which keeps
+//! the schema itself out of the context window. Credentials come from a
+//! `SecretRef` (`auth_ref`), never from the call's arguments.
+
+pub mod graphql;
+pub mod schema;
+
+use async_trait::async_trait;
+use rustant_core::config::GraphqlConfig;
+use rustant_core::credentials::configured_store;
+use rustant_core::error::ToolError;
+use rustant_core::secret_ref::{SecretRef, SecretResolver};
+use rustant_core::types::{RiskLevel, ToolErrorKind, ToolFailure, ToolOutput};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::registry::Tool;
+use graphql::{GraphqlError, QueryCost};
+use schema::{INTROSPECTION_QUERY, Schema};
+
+/// Largest introspection response read, in bytes. Schemas of large public
+/// APIs run to several megabytes.
+const MAX_INTROSPECTION_BYTES: usize = 32 * 1024 * 1024;
+
+/// Results returned by `schema_search` unless `limit` says otherwise.
+const DEFAULT_SEARCH_RESULTS: usize = 20;
+
+pub struct HttpApiTool {
+    config: Option<GraphqlConfig>,
+    /// Introspected schemas by endpoint URL.
+    schemas: Mutex<HashMap<String, Arc<Schema>>>,
+}
+
+impl Default for HttpApiTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpApiTool {
+    /// A tool that reads `[tools.graphql]` from the global config on each call.
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            schemas: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Use `config` instead of loading `[tools.graphql]`.
+    pub fn with_config(mut self, config: GraphqlConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    fn config(&self) -> GraphqlConfig {
+        self.config.clone().unwrap_or_else(|| {
+            rustant_core::config::load_config(None, None)
+                .map(|c| c.tools.graphql)
+                .unwrap_or_default()
+        })
+    }
+
+    fn cached_schema(&self, url: &str) -> Option<Arc<Schema>> {
+        let schemas = self.schemas.lock().unwrap_or_else(|e| e.into_inner());
+        schemas.get(url).cloned()
+    }
+
+    fn cache_schema(&self, url: &str, schema: Schema) -> Arc<Schema> {
+        let schema = Arc::new(schema);
+        let mut schemas = self.schemas.lock().unwrap_or_else(|e| e.into_inner());
+        schemas.insert(url.to_string(), schema.clone());
+        schema
+    }
+}
+
+#[async_trait]
+impl Tool for HttpApiTool {
+    fn name(&self) -> &str {
+        "http_api"
+    }
+    fn description(&self) -> &str {
+        "Make HTTP API requests. Actions: get, post, put, delete (returns status code and \
+         response body); introspect (fetch and cache a GraphQL endpoint's schema); \
+         schema_search (find GraphQL types and fields by keyword); graphql_query (validate \
+         a query against the schema, then send it, or a batch via 'queries'). Use auth_ref \
+         for credentials."
+    }
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["get", "post", "put", "delete", "introspect", "schema_search", "graphql_query"],
+                    "description": "HTTP method, or a GraphQL action"
+                },
+                "url": { "type": "string", "description": "Request URL (the GraphQL endpoint for GraphQL actions)" },
+                "body": { "type": "string", "description": "Request body (JSON string for post/put)" },
+                "headers": {
+                    "type": "object",
+                    "description": "Custom headers as key-value pairs",
+                    "additionalProperties": { "type": "string" }
+                },
+                "auth_ref": {
+                    "type": "string",
+                    "description": "Credential as a SecretRef: keychain:<account> or env:<VAR>. Sent as a bearer token unless auth_header is set"
+                },
+                "auth_header": {
+                    "type": "string",
+                    "description": "Header that carries the auth_ref secret as-is (e.g. X-Api-Key)"
+                },
+                "query": { "type": "string", "description": "GraphQL document (graphql_query)" },
+                "variables": { "type": "object", "description": "GraphQL variables (graphql_query)" },
+                "operation_name": {
+                    "type": "string",
+                    "description": "Operation to run when the document has several (graphql_query)"
+                },
+                "queries": {
+                    "type": "array",
+                    "description": "Batch of operations sent in one request (graphql_query)",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "query": { "type": "string" },
+                            "variables": { "type": "object" },
+                            "operation_name": { "type": "string" }
+                        },
+                        "required": ["query"]
+                    }
+                },
+                "keyword": { "type": "string", "description": "Type or field name to look for (schema_search)" },
+                "limit": { "type": "integer", "description": "Maximum results (schema_search, default 20)" }
+            },
+            "required": ["action", "url"]
+        })
+    }
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Execute
+    }
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolOutput, ToolError> {
+        let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("get");
+        let url = args.get("url").and_then(|v| v.as_str()).unwrap_or("");
+        if url.is_empty() {
+            return Ok(ToolOutput::text("Please provide a URL."));
+        }
+        let headers = match request_headers(&args) {
+            Ok(headers) => headers,
+            Err(message) => {
+                return Ok(ToolOutput::failure(
+                    message,
+                    ToolFailure::new(ToolErrorKind::InvalidArguments),
+                ));
+            }
+        };
+
+        match action {
+            "introspect" => return self.introspect(url, &headers).await,
+            "schema_search" => return self.schema_search(url, &headers, &args).await,
+            "graphql_query" => return self.graphql_query(url, &headers, &args).await,
+            _ => {}
+        }
+
+        let client = http_client()?;
+        let mut builder = match action {
+            "get" => client.get(url),
+            "post" => client.post(url),
+            "put" => client.put(url),
+            "delete" => client.delete(url),
+            _ => return Ok(ToolOutput::text(format!("Unknown method: {}", action))),
+        };
+
+        for (key, value) in &headers {
+            builder = builder.header(key.as_str(), value.as_str());
+        }
+
+        // Add body for post/put
+        if let Some(body) = args.get("body").and_then(|v| v.as_str()) {
+            builder = builder
+                .header("Content-Type", "application/json")
+                .body(body.to_string());
+        }
+
+        let response = builder.send().await.map_err(|e| {
+            let failure = if action == "post" && e.is_timeout() {
+                maybe_processed()
+            } else {
+                ToolFailure::from_reqwest(&e)
+            };
+            ToolError::failed("http_api", format!("HTTP request failed: {}", e), failure)
+        })?;
+
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok());
+        let headers_str: Vec<String> = response
+            .headers()
+            .iter()
+            .take(10)
+            .map(|(k, v)| format!("  {}: {}", k, v.to_str().unwrap_or("?")))
+            .collect();
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "<binary>".to_string());
+
+        // Truncate large responses
+        let body_display = if body.len() > 5000 {
+            format!(
+                "{}...\n(truncated, {} bytes total)",
+                &body[..5000],
+                body.len()
+            )
+        } else {
+            body
+        };
+
+        let text = format!(
+            "HTTP {} {} → {}\nHeaders:\n{}\nBody:\n{}",
+            action.to_uppercase(),
+            url,
+            status,
+            headers_str.join("\n"),
+            body_display
+        );
+        Ok(match http_failure(action, status.as_u16(), retry_after) {
+            Some(failure) => ToolOutput::failure(text, failure),
+            None => ToolOutput::text(text),
+        })
+    }
+}
+
+impl HttpApiTool {
+    async fn introspect(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+    ) -> Result<ToolOutput, ToolError> {
+        Ok(match self.fetch_schema(url, headers).await {
+            Ok(schema) => ToolOutput::text(format!(
+                "Introspected {}: {}\nUse schema_search to look up types and fields before \
+                 writing queries.",
+                url,
+                schema.summary()
+            )),
+            Err(output) => output,
+        })
+    }
+
+    async fn schema_search(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        args: &Value,
+    ) -> Result<ToolOutput, ToolError> {
+        let keyword = args
+            .get("keyword")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .unwrap_or("");
+        if keyword.is_empty() {
+            return Ok(ToolOutput::text("Please provide a keyword to search for."));
+        }
+        let limit = args
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_SEARCH_RESULTS);
+        let schema = match self.schema(url, headers).await {
+            Ok(schema) => schema,
+            Err(output) => return Ok(output),
+        };
+        let hits = schema.search(keyword, limit);
+        if hits.is_empty() {
+            return Ok(ToolOutput::text(format!(
+                "No types or fields match '{}' in the schema of {}.",
+                keyword, url
+            )));
+        }
+        Ok(ToolOutput::text(format!(
+            "{} match(es) for '{}' in the schema of {}:\n{}",
+            hits.len(),
+            keyword,
+            url,
+            hits.iter()
+                .map(|h| format!("- {}", h))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )))
+    }
+
+    async fn graphql_query(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        args: &Value,
+    ) -> Result<ToolOutput, ToolError> {
+        let config = self.config();
+        let batch = args
+            .get("queries")
+            .and_then(|v| v.as_array())
+            .filter(|items| !items.is_empty());
+        let operations = match batch {
+            Some(items) => items.iter().map(PendingOperation::from_args).collect(),
+            None => PendingOperation::from_args(args).map(|op| vec![op]),
+        };
+        let operations: Vec<PendingOperation> = match operations {
+            Ok(operations) => operations,
+            Err(message) => {
+                return Ok(ToolOutput::failure(
+                    message,
+                    ToolFailure::new(ToolErrorKind::InvalidArguments),
+                ));
+            }
+        };
+
+        let schema = match self.schema(url, headers).await {
+            Ok(schema) => schema,
+            Err(output) => return Ok(output),
+        };
+        let mut costs = Vec::new();
+        for (i, op) in operations.iter().enumerate() {
+            let checked = graphql::parse(&op.query).and_then(|document| {
+                graphql::check(
+                    &schema,
+                    &document,
+                    op.operation_name.as_deref(),
+                    &op.variables,
+                    &config,
+                )
+            });
+            match checked {
+                Ok(cost) => costs.push(cost),
+                Err(error) => return Ok(rejected(&error, batch.map(|_| i))),
+            }
+        }
+        let nodes = costs.iter().map(|c| c.nodes).fold(0, u64::saturating_add);
+        if nodes > config.max_nodes {
+            let error = GraphqlError::TooManyNodes {
+                nodes,
+                limit: config.max_nodes,
+            };
+            return Ok(rejected(&error, None));
+        }
+
+        let payload = match batch {
+            Some(_) => Value::Array(operations.iter().map(PendingOperation::payload).collect()),
+            None => operations[0].payload(),
+        };
+        let action = if costs
+            .iter()
+            .any(|c| c.kind == graphql::OperationKind::Mutation)
+        {
+            "post"
+        } else {
+            "get"
+        };
+        Ok(
+            match post_json(url, headers, &payload, config.max_response_bytes, action).await {
+                Ok(reply) => render_reply(url, &costs, &reply, batch.is_some(), action),
+                Err(output) => output,
+            },
+        )
+    }
+
+    /// The endpoint's schema, introspected on first use.
+    async fn schema(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+    ) -> Result<Arc<Schema>, ToolOutput> {
+        match self.cached_schema(url) {
+            Some(schema) => Ok(schema),
+            None => self.fetch_schema(url, headers).await,
+        }
+    }
+
+    async fn fetch_schema(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+    ) -> Result<Arc<Schema>, ToolOutput> {
+        let payload = json!({ "query": INTROSPECTION_QUERY });
+        let reply = post_json(url, headers, &payload, MAX_INTROSPECTION_BYTES, "get").await?;
+        match Schema::from_introspection(&reply.body) {
+            Ok(schema) => Ok(self.cache_schema(url, schema)),
+            Err(reason) => {
+                let errors = response_errors(&reply.body);
+                let mut message =
+                    format!("Could not read the GraphQL schema of {}: {}", url, reason);
+                for error in &errors {
+                    message.push_str(&format!("\n{}", error_line(error)));
+                }
+                let failure = error_failure(&errors, &reply, "get").with_remediation(
+                    "Check the URL and auth_ref; the server may not allow introspection.",
+                );
+                Err(ToolOutput::failure(message, failure))
+            }
+        }
+    }
+}
+
+/// One operation of a `graphql_query` call.
+struct PendingOperation {
+    query: String,
+    variables: Map<String, Value>,
+    operation_name: Option<String>,
+}
+
+impl PendingOperation {
+    fn from_args(args: &Value) -> Result<Self, String> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .filter(|q| !q.trim().is_empty())
+            .ok_or("Please provide a GraphQL query.")?;
+        let variables = match args.get("variables") {
+            None | Some(Value::Null) => Map::new(),
+            Some(Value::Object(map)) => map.clone(),
+            Some(Value::String(text)) => serde_json::from_str(text)
+                .map_err(|e| format!("variables must be a JSON object: {}", e))?,
+            Some(_) => return Err("variables must be a JSON object.".into()),
+        };
+        Ok(Self {
+            query: query.to_string(),
+            variables,
+            operation_name: args
+                .get("operation_name")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        })
+    }
+
+    fn payload(&self) -> Value {
+        let mut payload = json!({ "query": self.query, "variables": self.variables });
+        if let Some(name) = &self.operation_name {
+            payload["operationName"] = json!(name);
+        }
+        payload
+    }
+}
+
+/// A GraphQL response body, read under the size limit.
+struct Reply {
+    status: u16,
+    retry_after: Option<u64>,
+    body: Value,
+}
+
+fn http_client() -> Result<reqwest::Client, ToolError> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(25))
+        .build()
+        .map_err(|e| ToolError::ExecutionFailed {
+            name: "http_api".into(),
+            message: format!("Failed to create HTTP client: {}", e),
+        })
+}
+
+/// Custom headers plus the `auth_ref` credential. Inline secrets are
+/// refused so they never appear in transcripts.
+fn request_headers(args: &Value) -> Result<Vec<(String, String)>, String> {
+    let mut headers: Vec<(String, String)> = args
+        .get("headers")
+        .and_then(|v| v.as_object())
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+        .collect();
+    let Some(raw) = args.get("auth_ref").and_then(|v| v.as_str()) else {
+        return Ok(headers);
+    };
+    let secret = SecretRef::from(raw);
+    if !secret.is_keychain() && !secret.is_env() {
+        return Err(
+            "auth_ref must be a SecretRef (keychain:<account> or env:<VAR>); \
+             secrets are not accepted inline."
+                .into(),
+        );
+    }
+    let token = SecretResolver::resolve(&secret, configured_store().as_ref())
+        .map_err(|e| format!("Could not resolve auth_ref: {}", e))?;
+    match args.get("auth_header").and_then(|v| v.as_str()) {
+        Some(header) => headers.push((header.to_string(), token)),
+        None => headers.push(("Authorization".into(), format!("Bearer {}", token))),
+    }
+    Ok(headers)
+}
+
+/// POST a JSON payload and read a JSON reply of at most `max_bytes`.
+/// `action` is "post" for mutations, which may have been applied when
+/// the request times out.
+async fn post_json(
+    url: &str,
+    headers: &[(String, String)],
+    payload: &Value,
+    max_bytes: usize,
+    action: &str,
+) -> Result<Reply, ToolOutput> {
+    let client = http_client().map_err(|e| {
+        ToolOutput::failure(e.to_string(), ToolFailure::new(ToolErrorKind::Internal))
+    })?;
+    let mut builder = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header("Accept", "application/json")
+        .body(payload.to_string());
+    for (key, value) in headers {
+        builder = builder.header(key.as_str(), value.as_str());
+    }
+    let mut response = builder.send().await.map_err(|e| {
+        let failure = if action == "post" && e.is_timeout() {
+            maybe_processed()
+        } else {
+            ToolFailure::from_reqwest(&e)
+        };
+        ToolOutput::failure(format!("GraphQL request to {} failed: {}", url, e), failure)
+    })?;
+
+    let status = response.status().as_u16();
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok());
+    let too_large = || {
+        ToolOutput::failure(
+            format!(
+                "Response from {} exceeded the {}-byte limit and was discarded.",
+                url, max_bytes
+            ),
+            ToolFailure::new(ToolErrorKind::InvalidArguments).with_remediation(
+                "Select fewer fields or pass smaller first/last arguments \
+                 ([tools.graphql] max_response_bytes sets the limit).",
+            ),
+        )
+    };
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_bytes as u64)
+    {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| {
+        ToolOutput::failure(
+            format!("Failed to read response body: {}", e),
+            ToolFailure::from_reqwest(&e),
+        )
+    })? {
+        if bytes.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    match serde_json::from_slice(&bytes) {
+        Ok(body) => Ok(Reply {
+            status,
+            retry_after,
+            body,
+        }),
+        Err(_) => {
+            let text = String::from_utf8_lossy(&bytes);
+            let preview: String = text.chars().take(500).collect();
+            let failure = http_failure(action, status, retry_after)
+                .unwrap_or_else(|| ToolFailure::new(ToolErrorKind::Internal));
+            Err(ToolOutput::failure(
+                format!(
+                    "HTTP {} from {} is not a GraphQL response:\n{}",
+                    status, url, preview
+                ),
+                failure,
+            ))
+        }
+    }
+}
+
+/// A query rejected before sending, with the error in `graphql_error`.
+fn rejected(error: &GraphqlError, batch_index: Option<usize>) -> ToolOutput {
+    let remediation = match error {
+        GraphqlError::TooDeep { .. } | GraphqlError::TooManyNodes { .. } => {
+            "Select fewer nested fields or request smaller pages, and split the work across \
+             several queries if needed."
+        }
+        GraphqlError::Syntax { .. } => "Fix the query syntax and send it again.",
+        _ => "Use schema_search to look up the type's fields and arguments.",
+    };
+    let which = batch_index
+        .map(|i| format!(" (batch item {})", i + 1))
+        .unwrap_or_default();
+    let mut output = ToolOutput::failure(
+        format!("GraphQL query{} was not sent: {}", which, error),
+        ToolFailure::new(ToolErrorKind::InvalidArguments).with_remediation(remediation),
+    );
+    output.metadata.insert(
+        "graphql_error".into(),
+        serde_json::to_value(error).unwrap_or_default(),
+    );
+    output
+}
+
+/// How much of an operation's result came back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Complete,
+    /// Data and errors: fields at the error paths are null or missing.
+    Partial,
+    Failed,
+}
+
+/// Report the response to one request, which holds several operations'
+/// results when `batched`.
+fn render_reply(
+    url: &str,
+    costs: &[QueryCost],
+    reply: &Reply,
+    batched: bool,
+    action: &str,
+) -> ToolOutput {
+    let mut text = format!("GraphQL {} → HTTP {}", url, reply.status);
+    let bodies: Vec<&Value> = match (batched, reply.body.as_array()) {
+        (true, Some(items)) => items.iter().collect(),
+        (true, None) => {
+            text.push_str(
+                "\nThe server did not return a batch response; it may not support batched \
+                 queries. Send the operations one at a time.",
+            );
+            vec![&reply.body]
+        }
+        (false, _) => vec![&reply.body],
+    };
+    if batched && bodies.len() != costs.len() && reply.body.is_array() {
+        text.push_str(&format!(
+            "\nSent {} operations but received {} results.",
+            costs.len(),
+            bodies.len()
+        ));
+    }
+
+    let mut outcomes = Vec::new();
+    let mut all_errors = Vec::new();
+    for (i, body) in bodies.iter().enumerate() {
+        let label = match costs.get(i).filter(|_| bodies.len() == costs.len()) {
+            Some(cost) => {
+                let name = cost
+                    .operation
+                    .clone()
+                    .unwrap_or_else(|| format!("#{}", i + 1));
+                format!(
+                    "{} {} (depth {}, ~{} nodes estimated)",
+                    cost.kind.as_str(),
+                    name,
+                    cost.depth,
+                    cost.nodes
+                )
+            }
+            None => format!("result #{}", i + 1),
+        };
+        let (outcome, section) = render_operation(&label, body);
+        text.push_str("\n\n");
+        text.push_str(&section);
+        outcomes.push(outcome);
+        all_errors.extend(response_errors(body));
+    }
+
+    if outcomes.iter().all(|o| *o == Outcome::Failed) {
+        let mut output = ToolOutput::failure(text, error_failure(&all_errors, reply, action));
+        output
+            .metadata
+            .insert("graphql_errors".into(), json!(all_errors.len()));
+        return output;
+    }
+    let mut output = ToolOutput::text(text);
+    output.metadata.insert(
+        "graphql_partial".into(),
+        json!(outcomes.iter().any(|o| *o != Outcome::Complete)),
+    );
+    output
+        .metadata
+        .insert("graphql_errors".into(), json!(all_errors.len()));
+    output
+}
+
+/// One operation's result: whether data came back, its errors and data.
+fn render_operation(label: &str, body: &Value) -> (Outcome, String) {
+    let errors = response_errors(body);
+    let data = body.get("data").filter(|d| !d.is_null());
+    let (outcome, status) = match (data, errors.len()) {
+        (Some(_), 0) => (Outcome::Complete, "complete".to_string()),
+        (Some(_), n) => (
+            Outcome::Partial,
+            format!(
+                "PARTIAL — data returned with {} error(s); fields at the error paths are null \
+                 or missing",
+                n
+            ),
+        ),
+        (None, 0) => (
+            Outcome::Failed,
+            "failed — the response has neither data nor errors".to_string(),
+        ),
+        (None, n) => (
+            Outcome::Failed,
+            format!("failed with {} error(s), no data", n),
+        ),
+    };
+    let mut section = format!("{}: {}", label, status);
+    if !errors.is_empty() {
+        section.push_str("\nErrors:");
+        for error in &errors {
+            section.push('\n');
+            section.push_str(&error_line(error));
+        }
+    }
+    if let Some(data) = data {
+        section.push_str("\nData:\n");
+        section.push_str(&serde_json::to_string_pretty(data).unwrap_or_default());
+    }
+    (outcome, section)
+}
+
+fn response_errors(body: &Value) -> Vec<Value> {
+    body.get("errors")
+        .and_then(|e| e.as_array())
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// An error as "message (at path) [CODE]".
+fn error_line(error: &Value) -> String {
+    let message = error
+        .get("message")
+        .and_then(|m| m.as_str())
+        .unwrap_or("(no message)");
+    let mut line = format!("  - {}", message);
+    if let Some(path) = error.get("path").and_then(|p| p.as_array()) {
+        let path: Vec<String> = path
+            .iter()
+            .map(|p| match p {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+        line.push_str(&format!(" (at {})", path.join(".")));
+    }
+    if let Some(code) = error.pointer("/extensions/code").and_then(|c| c.as_str()) {
+        line.push_str(&format!(" [{}]", code));
+    }
+    line
+}
+
+/// Classify a response without data, from its error codes, then the HTTP
+/// status, then the first message.
+fn error_failure(errors: &[Value], reply: &Reply, action: &str) -> ToolFailure {
+    let code = errors
+        .iter()
+        .find_map(|e| e.pointer("/extensions/code").and_then(|c| c.as_str()))
+        .map(str::to_ascii_uppercase)
+        .unwrap_or_default();
+    let has = |needles: &[&str]| needles.iter().any(|n| code.contains(n));
+    let kind = if has(&["UNAUTHENTICATED", "UNAUTHORIZED", "FORBIDDEN"]) {
+        ToolErrorKind::PermissionDenied
+    } else if has(&["VALIDATION", "PARSE", "BAD_USER_INPUT"]) {
+        ToolErrorKind::InvalidArguments
+    } else if has(&["RATE_LIMIT", "THROTTL"]) {
+        ToolErrorKind::RateLimited
+    } else if has(&["NOT_FOUND"]) {
+        ToolErrorKind::NotFound
+    } else if let Some(failure) = http_failure(action, reply.status, reply.retry_after) {
+        return failure;
+    } else {
+        let message = errors
+            .first()
+            .and_then(|e| e.get("message"))
+            .and_then(|m| m.as_str())
+            .unwrap_or("");
+        ToolErrorKind::classify(message)
+    };
+    ToolFailure::new(kind)
+}
+
+/// Classify an error status. A POST that timed out or failed at a gateway
+/// may have been processed, so only explicit rejections (429, 503) stay
+/// retryable for it.
+fn http_failure(action: &str, status: u16, retry_after: Option<u64>) -> Option<ToolFailure> {
+    let failure = ToolFailure::from_http_status(status, retry_after)?;
+    if action == "post" && failure.kind.is_transient() && !matches!(status, 429 | 503) {
+        return Some(maybe_processed());
+    }
+    Some(failure)
+}
+
+fn maybe_processed() -> ToolFailure {
+    ToolFailure::new(ToolErrorKind::Internal)
+        .with_remediation("The POST may have been processed; check before sending it again.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_http_api_missing_url() {
+        let tool = HttpApiTool::new();
+        let result = tool
+            .execute(json!({"action": "get", "url": ""}))
+            .await
+            .unwrap();
+        assert!(result.content.contains("provide a URL"));
+    }
+
+    #[tokio::test]
+    async fn test_http_api_schema() {
+        let tool = HttpApiTool::new();
+        assert_eq!(tool.name(), "http_api");
+        assert_eq!(tool.risk_level(), RiskLevel::Execute);
+        let schema = tool.parameters_schema();
+        assert!(schema["properties"]["action"]["enum"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_http_api_invalid_url() {
+        let tool = HttpApiTool::new();
+        let result = tool
+            .execute(json!({"action": "get", "url": "not-a-url"}))
+            .await;
+        // Should return error or error message
+        assert!(result.is_err() || result.unwrap().content.contains("failed"));
+    }
+
+    #[test]
+    fn test_http_failure_kinds() {
+        let failure = http_failure("get", 429, Some(30)).unwrap();
+        assert_eq!(failure.kind, ToolErrorKind::RateLimited);
+        assert_eq!(failure.retry_after_secs, Some(30));
+        assert_eq!(
+            http_failure("get", 404, None).unwrap().kind,
+            ToolErrorKind::NotFound
+        );
+        assert_eq!(
+            http_failure("get", 504, None).unwrap().kind,
+            ToolErrorKind::TransientNetwork
+        );
+        // A POST through a failing gateway may have been processed.
+        assert_eq!(
+            http_failure("post", 504, None).unwrap().kind,
+            ToolErrorKind::Internal
+        );
+        assert!(http_failure("get", 204, None).is_none());
+    }
+
+    const URL: &str = "http://127.0.0.1:9/graphql";
+
+    /// A tool with the blog schema cached for `URL`, so nothing is fetched.
+    fn tool_with_schema() -> HttpApiTool {
+        let tool = HttpApiTool::new().with_config(GraphqlConfig::default());
+        tool.cache_schema(URL, schema::tests::blog_schema());
+        tool
+    }
+
+    #[tokio::test]
+    async fn test_graphql_query_rejects_unknown_field_before_sending() {
+        let output = tool_with_schema()
+            .execute(json!({
+                "action": "graphql_query",
+                "url": URL,
+                "query": "query($id: ID!) { user(id: $id) { id fullName } }",
+                "variables": {"id": "1"}
+            }))
+            .await
+            .unwrap();
+        assert!(output.content.contains("was not sent"));
+        assert!(output.content.contains("valid fields: id, name, email"));
+        assert_eq!(output.error.unwrap().kind, ToolErrorKind::InvalidArguments);
+        assert_eq!(output.metadata["graphql_error"]["kind"], "unknown_field");
+        assert_eq!(
+            output.metadata["graphql_error"]["path"],
+            "Query.user.fullName"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_graphql_batch_is_checked_as_a_whole() {
+        let tool = HttpApiTool::new().with_config(GraphqlConfig {
+            max_nodes: 150,
+            ..GraphqlConfig::default()
+        });
+        tool.cache_schema(URL, schema::tests::blog_schema());
+        let query = json!({"query": "{ users(first: 50) { id } }"});
+        let output = tool
+            .execute(json!({
+                "action": "graphql_query",
+                "url": URL,
+                "queries": [query, {"query": "{ user { id } }"}]
+            }))
+            .await
+            .unwrap();
+        assert!(
+            output.content.contains("batch item 2"),
+            "{}",
+            output.content
+        );
+
+        // Each item is within the limit, the batch is not.
+        let output = tool
+            .execute(json!({
+                "action": "graphql_query",
+                "url": URL,
+                "queries": [query, query]
+            }))
+            .await
+            .unwrap();
+        assert!(
+            output.content.contains("about 200 nodes"),
+            "{}",
+            output.content
+        );
+    }
+
+    #[tokio::test]
+    async fn test_schema_search_uses_cached_schema() {
+        let output = tool_with_schema()
+            .execute(json!({"action": "schema_search", "url": URL, "keyword": "email"}))
+            .await
+            .unwrap();
+        assert!(output.content.contains("field User.email: String"));
+    }
+
+    #[tokio::test]
+    async fn test_inline_auth_is_refused() {
+        let output = tool_with_schema()
+            .execute(json!({
+                "action": "schema_search",
+                "url": URL,
+                "keyword": "user",
+                "auth_ref": "ghp_plaintext"
+            }))
+            .await
+            .unwrap();
+        assert!(output.content.contains("not accepted inline"));
+        assert!(!output.content.contains("ghp_plaintext"));
+    }
+
+    fn cost(name: &str) -> QueryCost {
+        QueryCost {
+            operation: Some(name.into()),
+            kind: graphql::OperationKind::Query,
+            depth: 2,
+            nodes: 3,
+        }
+    }
+
+    fn reply(body: Value) -> Reply {
+        Reply {
+            status: 200,
+            retry_after: None,
+            body,
+        }
+    }
+
+    #[test]
+    fn test_partial_response_reports_data_and_errors() {
+        let body = json!({
+            "data": {"user": {"name": "Ada", "email": null}},
+            "errors": [{"message": "Not authorized", "path": ["user", "email"], "extensions": {"code": "FORBIDDEN"}}]
+        });
+        let output = render_reply(URL, &[cost("User")], &reply(body), false, "get");
+        assert!(output.error.is_none());
+        assert!(
+            output
+                .content
+                .contains("query User (depth 2, ~3 nodes estimated): PARTIAL")
+        );
+        assert!(
+            output
+                .content
+                .contains("Not authorized (at user.email) [FORBIDDEN]")
+        );
+        assert!(output.content.contains("\"name\": \"Ada\""));
+        assert_eq!(output.metadata["graphql_partial"], true);
+        assert_eq!(output.metadata["graphql_errors"], 1);
+
+        let body = json!({"errors": [{"message": "Not authorized", "extensions": {"code": "UNAUTHENTICATED"}}]});
+        let output = render_reply(URL, &[cost("User")], &reply(body), false, "get");
+        assert_eq!(output.error.unwrap().kind, ToolErrorKind::PermissionDenied);
+        assert!(output.content.contains("failed with 1 error(s), no data"));
+    }
+
+    #[test]
+    fn test_batch_response_reports_each_operation() {
+        let body = json!([
+            {"data": {"users": []}},
+            {"data": null, "errors": [{"message": "boom"}]}
+        ]);
+        let output = render_reply(URL, &[cost("A"), cost("B")], &reply(body), true, "get");
+        assert!(
+            output
+                .content
+                .contains("query A (depth 2, ~3 nodes estimated): complete")
+        );
+        assert!(
+            output
+                .content
+                .contains("query B (depth 2, ~3 nodes estimated): failed")
+        );
+        assert_eq!(output.metadata["graphql_partial"], true);
+
+        // A server without batching answers with a single error object.
+        let body = json!({"errors": [{"message": "batching is not supported"}]});
+        let output = render_reply(URL, &[cost("A"), cost("B")], &reply(body), true, "get");
+        assert!(output.content.contains("did not return a batch response"));
+        assert!(output.error.is_some());
+    }
+}
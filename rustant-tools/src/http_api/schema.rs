@@ -0,0 +1,505 @@
+//! GraphQL schemas read from introspection, and keyword search over them.
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Introspection query sent by the `introspect` action. Type references
+/// are unwrapped seven levels deep, enough for `[[T!]!]!`.
+pub const INTROSPECTION_QUERY: &str = r#"query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      kind
+      name
+      description
+      fields(includeDeprecated: true) {
+        name
+        description
+        args { name description type { ...TypeRef } defaultValue }
+        type { ...TypeRef }
+      }
+      inputFields { name description type { ...TypeRef } defaultValue }
+      enumValues(includeDeprecated: true) { name }
+      possibleTypes { name }
+    }
+  }
+}
+
+fragment TypeRef on __Type {
+  kind name
+  ofType { kind name ofType { kind name ofType { kind name ofType {
+    kind name ofType { kind name ofType { kind name ofType { kind name } } }
+  } } } }
+}"#;
+
+/// A reference to a type, with its list and non-null wrappers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeRef {
+    /// The named type inside any wrappers.
+    pub name: String,
+    /// The reference as written in SDL, e.g. `[User!]!`.
+    pub display: String,
+    /// Whether any wrapper is a list.
+    pub list: bool,
+    /// Whether the outermost wrapper is non-null.
+    pub non_null: bool,
+}
+
+impl TypeRef {
+    fn from_json(value: &Value) -> Option<Self> {
+        match value.get("kind")?.as_str()? {
+            "NON_NULL" => {
+                let inner = Self::from_json(value.get("ofType")?)?;
+                Some(Self {
+                    display: format!("{}!", inner.display),
+                    non_null: true,
+                    ..inner
+                })
+            }
+            "LIST" => {
+                let inner = Self::from_json(value.get("ofType")?)?;
+                Some(Self {
+                    display: format!("[{}]", inner.display),
+                    list: true,
+                    non_null: false,
+                    ..inner
+                })
+            }
+            _ => {
+                let name = value.get("name")?.as_str()?.to_string();
+                Some(Self {
+                    display: name.clone(),
+                    name,
+                    list: false,
+                    non_null: false,
+                })
+            }
+        }
+    }
+}
+
+/// A field argument or input object field.
+#[derive(Debug, Clone)]
+pub struct InputValue {
+    pub name: String,
+    pub description: Option<String>,
+    pub ty: TypeRef,
+    pub has_default: bool,
+}
+
+impl InputValue {
+    /// Whether a value must be given: non-null and without a default.
+    pub fn is_required(&self) -> bool {
+        self.ty.non_null && !self.has_default
+    }
+}
+
+/// A field of an object or interface type.
+#[derive(Debug, Clone)]
+pub struct FieldDef {
+    pub name: String,
+    pub description: Option<String>,
+    pub args: Vec<InputValue>,
+    pub ty: TypeRef,
+}
+
+impl FieldDef {
+    /// The field as written in SDL, e.g. `user(id: ID!): User`.
+    pub fn signature(&self) -> String {
+        if self.args.is_empty() {
+            return format!("{}: {}", self.name, self.ty.display);
+        }
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|a| format!("{}: {}", a.name, a.ty.display))
+            .collect();
+        format!("{}({}): {}", self.name, args.join(", "), self.ty.display)
+    }
+}
+
+/// A named type in the schema.
+#[derive(Debug, Clone)]
+pub struct TypeDef {
+    pub name: String,
+    /// Introspection kind: `OBJECT`, `INTERFACE`, `UNION`, `SCALAR`, `ENUM`
+    /// or `INPUT_OBJECT`.
+    pub kind: String,
+    pub description: Option<String>,
+    pub fields: Vec<FieldDef>,
+    pub input_fields: Vec<InputValue>,
+    pub enum_values: Vec<String>,
+    pub possible_types: Vec<String>,
+}
+
+impl TypeDef {
+    /// Whether the type is selected from with a selection set.
+    pub fn is_composite(&self) -> bool {
+        matches!(self.kind.as_str(), "OBJECT" | "INTERFACE" | "UNION")
+    }
+
+    pub fn field(&self, name: &str) -> Option<&FieldDef> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Names valid in a selection on this type, for error messages.
+    pub fn selectable(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.fields.iter().map(|f| f.name.clone()).collect();
+        names.extend(self.possible_types.iter().map(|t| format!("... on {}", t)));
+        names.push("__typename".into());
+        names
+    }
+}
+
+/// A GraphQL schema, as reported by an endpoint's introspection.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    pub query_type: Option<String>,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    /// Named types, without the introspection types (`__Type` etc.).
+    pub types: BTreeMap<String, TypeDef>,
+}
+
+impl Schema {
+    /// Read a schema from an introspection response, with or without its
+    /// `data` envelope.
+    pub fn from_introspection(response: &Value) -> Result<Self, String> {
+        let schema = response
+            .get("data")
+            .unwrap_or(response)
+            .get("__schema")
+            .filter(|s| s.is_object())
+            .ok_or_else(|| "response has no __schema; introspection may be disabled".to_string())?;
+        let root = |key: &str| {
+            schema
+                .get(key)
+                .and_then(|t| t.get("name"))
+                .and_then(|n| n.as_str())
+                .map(str::to_string)
+        };
+        let mut types = BTreeMap::new();
+        for ty in schema
+            .get("types")
+            .and_then(|t| t.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let Some(name) = ty.get("name").and_then(|n| n.as_str()) else {
+                continue;
+            };
+            if name.starts_with("__") {
+                continue;
+            }
+            let list = |key: &str| ty.get(key).and_then(|v| v.as_array()).into_iter().flatten();
+            let names = |key: &str| {
+                list(key)
+                    .filter_map(|v| v.get("name").and_then(|n| n.as_str()))
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            };
+            let fields = list("fields")
+                .filter_map(|f| {
+                    Some(FieldDef {
+                        name: f.get("name")?.as_str()?.to_string(),
+                        description: description(f),
+                        args: input_values(f.get("args")),
+                        ty: TypeRef::from_json(f.get("type")?)?,
+                    })
+                })
+                .collect();
+            types.insert(
+                name.to_string(),
+                TypeDef {
+                    name: name.to_string(),
+                    kind: ty
+                        .get("kind")
+                        .and_then(|k| k.as_str())
+                        .unwrap_or("OBJECT")
+                        .to_string(),
+                    description: description(ty),
+                    fields,
+                    input_fields: input_values(ty.get("inputFields")),
+                    enum_values: names("enumValues"),
+                    possible_types: names("possibleTypes"),
+                },
+            );
+        }
+        if types.is_empty() {
+            return Err("introspection returned no types".into());
+        }
+        Ok(Self {
+            query_type: root("queryType"),
+            mutation_type: root("mutationType"),
+            subscription_type: root("subscriptionType"),
+            types,
+        })
+    }
+
+    pub fn type_def(&self, name: &str) -> Option<&TypeDef> {
+        self.types.get(name)
+    }
+
+    /// Short overview: type count and the root operations' fields.
+    pub fn summary(&self) -> String {
+        let mut out = format!("{} types.", self.types.len());
+        for (label, root) in [
+            ("Queries", &self.query_type),
+            ("Mutations", &self.mutation_type),
+        ] {
+            let Some(ty) = root.as_deref().and_then(|r| self.type_def(r)) else {
+                continue;
+            };
+            let names: Vec<&str> = ty.fields.iter().map(|f| f.name.as_str()).collect();
+            out.push_str(&format!(
+                "\n{} ({}): {}",
+                label,
+                names.len(),
+                truncate_list(&names, 40)
+            ));
+        }
+        out
+    }
+
+    /// Types, fields, arguments and enum values whose name or description
+    /// contains `keyword`, best matches first.
+    pub fn search(&self, keyword: &str, limit: usize) -> Vec<String> {
+        let needle = keyword.to_lowercase();
+        let score = |name: &str, description: &Option<String>| -> Option<u8> {
+            let name = name.to_lowercase();
+            if name == needle {
+                Some(0)
+            } else if name.contains(&needle) {
+                Some(1)
+            } else if description
+                .as_deref()
+                .is_some_and(|d| d.to_lowercase().contains(&needle))
+            {
+                Some(2)
+            } else {
+                None
+            }
+        };
+
+        let mut hits: Vec<(u8, String)> = Vec::new();
+        for ty in self.types.values() {
+            if let Some(s) = score(&ty.name, &ty.description) {
+                hits.push((s, describe_type(ty)));
+            }
+            for field in &ty.fields {
+                if let Some(s) = score(&field.name, &field.description) {
+                    hits.push((
+                        s + 1,
+                        with_description(
+                            format!("field {}.{}", ty.name, field.signature()),
+                            &field.description,
+                        ),
+                    ));
+                }
+            }
+            for input in &ty.input_fields {
+                if let Some(s) = score(&input.name, &input.description) {
+                    hits.push((
+                        s + 1,
+                        with_description(
+                            format!(
+                                "input field {}.{}: {}",
+                                ty.name, input.name, input.ty.display
+                            ),
+                            &input.description,
+                        ),
+                    ));
+                }
+            }
+            for value in &ty.enum_values {
+                if let Some(s) = score(value, &None) {
+                    hits.push((s + 2, format!("enum value {}.{}", ty.name, value)));
+                }
+            }
+        }
+        hits.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        hits.into_iter().take(limit).map(|(_, hit)| hit).collect()
+    }
+}
+
+/// A type's kind, description and members, on a few lines.
+fn describe_type(ty: &TypeDef) -> String {
+    let mut out = with_description(
+        format!("{} {}", ty.kind.to_lowercase().replace('_', " "), ty.name),
+        &ty.description,
+    );
+    let members: Vec<String> = match ty.kind.as_str() {
+        "ENUM" => ty.enum_values.clone(),
+        "UNION" => ty.possible_types.clone(),
+        "INPUT_OBJECT" => ty
+            .input_fields
+            .iter()
+            .map(|f| format!("{}: {}", f.name, f.ty.display))
+            .collect(),
+        _ => ty.fields.iter().map(FieldDef::signature).collect(),
+    };
+    if !members.is_empty() {
+        let members: Vec<&str> = members.iter().map(String::as_str).collect();
+        out.push_str(&format!("\n    {}", truncate_list(&members, 15)));
+    }
+    out
+}
+
+fn with_description(line: String, description: &Option<String>) -> String {
+    match description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        Some(d) => format!("{} — {}", line, d.lines().next().unwrap_or(d)),
+        None => line,
+    }
+}
+
+fn truncate_list(items: &[&str], max: usize) -> String {
+    let mut out = items
+        .iter()
+        .take(max)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if items.len() > max {
+        out.push_str(&format!(", … ({} more)", items.len() - max));
+    }
+    out
+}
+
+fn description(value: &Value) -> Option<String> {
+    value
+        .get("description")
+        .and_then(|d| d.as_str())
+        .map(str::to_string)
+}
+
+fn input_values(value: Option<&Value>) -> Vec<InputValue> {
+    value
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|a| {
+            Some(InputValue {
+                name: a.get("name")?.as_str()?.to_string(),
+                description: description(a),
+                ty: TypeRef::from_json(a.get("type")?)?,
+                has_default: a.get("defaultValue").is_some_and(|d| !d.is_null()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn named(kind: &str, name: &str) -> Value {
+        json!({"kind": kind, "name": name})
+    }
+
+    fn non_null(inner: Value) -> Value {
+        json!({"kind": "NON_NULL", "ofType": inner})
+    }
+
+    fn list(inner: Value) -> Value {
+        json!({"kind": "LIST", "ofType": inner})
+    }
+
+    /// A small blog schema used by the tool's tests.
+    pub(crate) fn blog_schema() -> Schema {
+        let id = non_null(named("SCALAR", "ID"));
+        let string = named("SCALAR", "String");
+        let int = named("SCALAR", "Int");
+        let response = json!({"data": {"__schema": {
+            "queryType": {"name": "Query"},
+            "mutationType": {"name": "Mutation"},
+            "subscriptionType": null,
+            "types": [
+                {"kind": "OBJECT", "name": "Query", "fields": [
+                    {"name": "user", "args": [{"name": "id", "type": id}], "type": named("OBJECT", "User")},
+                    {"name": "users", "args": [{"name": "first", "type": int}], "type": non_null(list(non_null(named("OBJECT", "User"))))},
+                    {"name": "search", "args": [{"name": "term", "type": non_null(string.clone())}], "type": list(named("UNION", "SearchResult"))},
+                ]},
+                {"kind": "OBJECT", "name": "Mutation", "fields": [
+                    {"name": "createPost", "args": [{"name": "title", "type": non_null(string.clone())}], "type": named("OBJECT", "Post")},
+                ]},
+                {"kind": "OBJECT", "name": "User", "description": "A registered account.", "fields": [
+                    {"name": "id", "args": [], "type": id},
+                    {"name": "name", "args": [], "type": string},
+                    {"name": "email", "description": "Primary email address.", "args": [], "type": string},
+                    {"name": "posts", "args": [{"name": "first", "type": int, "defaultValue": "10"}], "type": list(named("OBJECT", "Post"))},
+                    {"name": "followers", "args": [{"name": "first", "type": int}], "type": named("OBJECT", "UserConnection")},
+                ]},
+                {"kind": "OBJECT", "name": "UserConnection", "fields": [
+                    {"name": "totalCount", "args": [], "type": int},
+                    {"name": "nodes", "args": [], "type": list(named("OBJECT", "User"))},
+                ]},
+                {"kind": "OBJECT", "name": "Post", "fields": [
+                    {"name": "id", "args": [], "type": id},
+                    {"name": "title", "args": [], "type": string},
+                    {"name": "status", "args": [], "type": named("ENUM", "PostStatus")},
+                    {"name": "author", "args": [], "type": named("OBJECT", "User")},
+                ]},
+                {"kind": "UNION", "name": "SearchResult", "possibleTypes": [{"name": "User"}, {"name": "Post"}]},
+                {"kind": "ENUM", "name": "PostStatus", "enumValues": [{"name": "DRAFT"}, {"name": "PUBLISHED"}]},
+                {"kind": "SCALAR", "name": "ID"},
+                {"kind": "SCALAR", "name": "String"},
+                {"kind": "SCALAR", "name": "Int"},
+                {"kind": "OBJECT", "name": "__Type", "fields": []},
+            ]
+        }}});
+        Schema::from_introspection(&response).unwrap()
+    }
+
+    #[test]
+    fn test_schema_from_introspection() {
+        let schema = blog_schema();
+        assert_eq!(schema.query_type.as_deref(), Some("Query"));
+        assert!(schema.type_def("__Type").is_none());
+        let users = schema.type_def("Query").unwrap().field("users").unwrap();
+        assert_eq!(users.ty.display, "[User!]!");
+        assert_eq!(users.ty.name, "User");
+        assert!(users.ty.list && users.ty.non_null);
+        assert_eq!(
+            schema
+                .type_def("Query")
+                .unwrap()
+                .field("user")
+                .unwrap()
+                .signature(),
+            "user(id: ID!): User"
+        );
+        let posts = schema.type_def("User").unwrap().field("posts").unwrap();
+        assert!(!posts.args[0].is_required());
+        assert!(
+            schema
+                .summary()
+                .contains("Queries (3): user, users, search")
+        );
+
+        let err = Schema::from_introspection(&json!({"errors": [{"message": "disabled"}]}));
+        assert!(err.unwrap_err().contains("introspection may be disabled"));
+    }
+
+    #[test]
+    fn test_schema_search_ranks_names_first() {
+        let schema = blog_schema();
+        let hits = schema.search("email", 10);
+        assert_eq!(hits[0], "field User.email: String — Primary email address.");
+
+        let hits = schema.search("user", 3);
+        assert_eq!(hits.len(), 3);
+        assert!(hits[0].starts_with("object User — A registered account."));
+        assert!(hits[0].contains("posts(first: Int): [Post]"));
+
+        let hits = schema.search("published", 10);
+        assert_eq!(hits, vec!["enum value PostStatus.PUBLISHED".to_string()]);
+        assert!(schema.search("nothing-like-this", 10).is_empty());
+    }
+}
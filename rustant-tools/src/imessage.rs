@@ -1,14 +1,19 @@
-//! iMessage tools — contact lookup and message sending via macOS Messages.app.
+//! iMessage tools — contact lookup, message sending and reading via macOS
+//! Messages.app.
 //!
 //! These tools expose the iMessage channel functionality as agent tools,
-//! allowing the LLM to search contacts by name and send iMessages directly.
-//! macOS only.
+//! allowing the LLM to search contacts by name, send iMessages directly and
+//! read incoming ones from the Messages database. macOS only.
 
 use crate::registry::Tool;
 use async_trait::async_trait;
+use chrono::{DateTime, Local};
 use rustant_core::error::ToolError;
-use rustant_core::types::{RiskLevel, ToolOutput};
+use rustant_core::types::{RiskLevel, ToolErrorKind, ToolFailure, ToolOutput};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
 use tracing::debug;
 
@@ -172,8 +177,56 @@ impl Tool for IMessageSendTool {
 
 // ── Read Messages Tool ─────────────────────────────────────────────────────
 
-/// Tool that reads recent incoming iMessages from the Messages database.
-pub struct IMessageReadTool;
+/// Seconds between the Unix epoch and Apple's Core Data epoch (2001-01-01).
+const APPLE_EPOCH_OFFSET: i64 = 978_307_200;
+
+/// Longest message preview in the `threads` listing.
+const PREVIEW_CHARS: usize = 80;
+
+/// Tool that reads iMessages from the Messages database.
+///
+/// `recent` reads the last few minutes, `new` reads what arrived since the
+/// previous `new` call in each chat, and `threads` lists conversations with
+/// unread counts. Tapbacks are attached to the message they react to, and
+/// edited, unsent and unread messages are marked.
+pub struct IMessageReadTool {
+    workspace: PathBuf,
+}
+
+impl IMessageReadTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+
+    fn sync_path(&self) -> PathBuf {
+        self.workspace
+            .join(".rustant")
+            .join("imessage")
+            .join("sync.json")
+    }
+
+    fn load_sync(&self) -> SyncState {
+        std::fs::read_to_string(self.sync_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_sync(&self, state: &SyncState) -> Result<(), ToolError> {
+        let path = self.sync_path();
+        let failed = |e: std::io::Error| ToolError::ExecutionFailed {
+            name: "imessage_read".into(),
+            message: format!("Failed to save iMessage sync state: {e}"),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(failed)?;
+        }
+        let json = serde_json::to_string_pretty(state).unwrap_or_default();
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(failed)?;
+        std::fs::rename(&tmp, &path).map_err(failed)
+    }
+}
 
 #[async_trait]
 impl Tool for IMessageReadTool {
@@ -182,22 +235,40 @@ impl Tool for IMessageReadTool {
     }
 
     fn description(&self) -> &str {
-        "Read recent incoming iMessages. Returns the latest messages received \
-         in the past N minutes (default 5). Useful for checking replies."
+        "Read incoming iMessages. Actions: 'recent' (default) returns messages \
+         received in the past N minutes; 'new' returns only messages that arrived \
+         since the last 'new' call, per chat; 'threads' lists recent conversations \
+         with unread counts and a preview of the last message. Messages show \
+         tapback reactions, edited/unsent state and whether they are unread."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
         json!({
             "type": "object",
             "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["recent", "new", "threads"],
+                    "description": "What to read (default: recent)",
+                    "default": "recent"
+                },
                 "minutes": {
                     "type": "integer",
-                    "description": "How far back to look in minutes (default: 5, max: 60)",
+                    "description": "For 'recent', and the first 'new': how far back to look in minutes (default: 5, max: 60)",
                     "default": 5
                 },
+                "days": {
+                    "type": "integer",
+                    "description": "For 'threads': conversations active in the past N days (default: 7)",
+                    "default": 7
+                },
+                "chat": {
+                    "type": "string",
+                    "description": "Only this conversation: a phone number, email, group name or chat GUID"
+                },
                 "limit": {
                     "type": "integer",
-                    "description": "Maximum number of messages to return (default: 20)",
+                    "description": "Maximum number of messages or threads to return (default: 20)",
                     "default": 20
                 }
             }
@@ -205,45 +276,736 @@ impl Tool for IMessageReadTool {
     }
 
     async fn execute(&self, args: serde_json::Value) -> Result<ToolOutput, ToolError> {
+        let action = args["action"].as_str().unwrap_or("recent");
         let minutes = args["minutes"].as_u64().unwrap_or(5).min(60);
         let limit = args["limit"].as_u64().unwrap_or(20).min(100);
+        let chat = args["chat"].as_str().filter(|c| !c.trim().is_empty());
+
+        debug!(action, minutes, limit, "Reading iMessages");
+
+        let db = MessagesDb::open()?;
+        match action {
+            "recent" => {
+                let filter = format!("m.is_from_me = 0 AND {}", since_minutes(minutes));
+                let messages = db.messages(&filter, chat, limit, false).await?;
+                if messages.is_empty() {
+                    return Ok(ToolOutput::text(format!(
+                        "No incoming messages in the last {} minute(s).",
+                        minutes
+                    )));
+                }
+                Ok(ToolOutput::text(format!(
+                    "Recent iMessages (last {} minute(s), {} message(s)):\n\n{}",
+                    minutes,
+                    messages.len(),
+                    format_messages(&messages)
+                )))
+            }
+            "new" => {
+                let mut sync = self.load_sync();
+                let filter = match sync.floor_for(chat) {
+                    Some(after) => format!("m.is_from_me = 0 AND m.ROWID > {}", after),
+                    // First sync: start from the recent window.
+                    None => format!("m.is_from_me = 0 AND {}", since_minutes(minutes)),
+                };
+                let rows = db.rows(&filter, chat, limit, true).await?;
+                let rows: Vec<MessageRow> = rows.into_iter().filter(|r| sync.is_new(r)).collect();
+                let latest = db.latest_rowid().await?;
+                sync.advance(&rows, chat, latest);
+                let messages = db.assemble(rows).await?;
+                self.save_sync(&sync)?;
+                if messages.is_empty() {
+                    return Ok(ToolOutput::text("No new incoming messages."));
+                }
+                Ok(ToolOutput::text(format!(
+                    "New iMessages ({} message(s)):\n\n{}",
+                    messages.len(),
+                    format_messages(&messages)
+                )))
+            }
+            "threads" => {
+                let days = args["days"].as_u64().unwrap_or(7).clamp(1, 365);
+                let threads = db.threads(days, chat, limit).await?;
+                if threads.is_empty() {
+                    return Ok(ToolOutput::text(format!(
+                        "No conversations in the last {} day(s).",
+                        days
+                    )));
+                }
+                let unread: u64 = threads.iter().map(|t| t.unread).sum();
+                let mut output = format!(
+                    "{} conversation(s) in the last {} day(s), {} unread message(s):\n\n",
+                    threads.len(),
+                    days,
+                    unread
+                );
+                for thread in &threads {
+                    output.push_str(&thread.to_string());
+                    output.push('\n');
+                }
+                let mut result = ToolOutput::text(output);
+                result.metadata.insert(
+                    "threads".into(),
+                    serde_json::to_value(&threads).unwrap_or_default(),
+                );
+                Ok(result)
+            }
+            other => Err(ToolError::InvalidArguments {
+                name: "imessage_read".into(),
+                reason: format!(
+                    "unknown action '{}'; use 'recent', 'new' or 'threads'",
+                    other
+                ),
+            }),
+        }
+    }
 
-        debug!(minutes = minutes, limit = limit, "Reading recent iMessages");
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::ReadOnly
+    }
 
-        let messages = read_recent_imessages(minutes, limit).await.map_err(|e| {
-            ToolError::ExecutionFailed {
-                name: "imessage_read".into(),
-                message: e,
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(15)
+    }
+}
+
+/// Last-seen message ROWID per chat, persisted in
+/// `.rustant/imessage/sync.json`, so `new` only reads rows added since.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    /// Chat GUID → highest ROWID already returned.
+    #[serde(default)]
+    chats: BTreeMap<String, i64>,
+    /// ROWID every chat has been read up to by an unfiltered sync.
+    #[serde(default)]
+    floor: Option<i64>,
+}
+
+impl SyncState {
+    /// ROWID after which to read, or `None` before the first sync.
+    fn floor_for(&self, chat: Option<&str>) -> Option<i64> {
+        let floor = self.floor?;
+        let lowest = match chat {
+            // The chat filter matches several columns, so any cursor may apply.
+            Some(_) => self.chats.values().copied().min(),
+            None => None,
+        };
+        Some(lowest.map_or(floor, |c| c.min(floor)))
+    }
+
+    fn cursor(&self, chat: &str) -> i64 {
+        let floor = self.floor.unwrap_or(0);
+        self.chats.get(chat).copied().unwrap_or(floor).max(floor)
+    }
+
+    fn is_new(&self, row: &MessageRow) -> bool {
+        self.floor.is_none() || row.rowid > self.cursor(&row.chat)
+    }
+
+    /// Record `rows` as read. An unfiltered sync that was not cut short by
+    /// the limit has seen everything up to `latest`.
+    fn advance(&mut self, rows: &[MessageRow], chat: Option<&str>, latest: i64) {
+        for row in rows {
+            let cursor = self.chats.entry(row.chat.clone()).or_insert(row.rowid);
+            *cursor = (*cursor).max(row.rowid);
+        }
+        if chat.is_none() {
+            let seen = rows.iter().map(|r| r.rowid).max();
+            let next = match (self.floor, seen) {
+                (None, _) => latest,
+                (Some(_), Some(seen)) => seen,
+                (Some(_), None) => latest,
+            };
+            self.floor = Some(self.floor.unwrap_or(0).max(next));
+        }
+    }
+}
+
+/// A row of the `message` table, as selected by [`MessagesDb::rows`].
+#[derive(Debug, Clone, Default, Deserialize)]
+struct MessageRow {
+    rowid: i64,
+    #[serde(default)]
+    guid: String,
+    #[serde(default)]
+    text: Option<String>,
+    /// `attributedBody` as hex.
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    is_from_me: i64,
+    #[serde(default)]
+    is_read: i64,
+    #[serde(default)]
+    date: i64,
+    #[serde(default)]
+    date_edited: i64,
+    #[serde(default)]
+    date_retracted: i64,
+    #[serde(default)]
+    assoc_guid: Option<String>,
+    #[serde(default)]
+    assoc_type: i64,
+    #[serde(default)]
+    sender: String,
+    #[serde(default)]
+    chat: String,
+    #[serde(default)]
+    chat_name: String,
+}
+
+impl MessageRow {
+    /// The message text, decoded from `attributedBody` when `text` is empty.
+    fn body_text(&self) -> Option<String> {
+        self.text
+            .clone()
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| {
+                let bytes = decode_hex(self.body.as_deref()?)?;
+                decode_attributed_body(&bytes)
+            })
+    }
+
+    /// The tapback this row adds (`true`) or removes (`false`), if it is one.
+    fn tapback(&self) -> Option<(Tapback, bool)> {
+        match self.assoc_type {
+            2000..=2006 => Tapback::from_code(self.assoc_type - 2000).map(|t| (t, true)),
+            3000..=3006 => Tapback::from_code(self.assoc_type - 3000).map(|t| (t, false)),
+            _ => None,
+        }
+    }
+
+    /// GUID of the message a tapback reacts to: `p:0/<guid>` or `bp:<guid>`.
+    fn target_guid(&self) -> Option<&str> {
+        let assoc = self.assoc_guid.as_deref()?;
+        let guid = assoc
+            .rsplit_once('/')
+            .map(|(_, g)| g)
+            .or_else(|| assoc.strip_prefix("bp:"))
+            .unwrap_or(assoc);
+        (!guid.is_empty()).then_some(guid)
+    }
+}
+
+/// A tapback reaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tapback {
+    Loved,
+    Liked,
+    Disliked,
+    Laughed,
+    Emphasized,
+    Questioned,
+    /// An emoji or sticker reaction (macOS 14+).
+    Reacted,
+}
+
+impl Tapback {
+    fn from_code(code: i64) -> Option<Self> {
+        Some(match code {
+            0 => Tapback::Loved,
+            1 => Tapback::Liked,
+            2 => Tapback::Disliked,
+            3 => Tapback::Laughed,
+            4 => Tapback::Emphasized,
+            5 => Tapback::Questioned,
+            6 => Tapback::Reacted,
+            _ => return None,
+        })
+    }
+}
+
+impl std::fmt::Display for Tapback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verb = match self {
+            Tapback::Loved => "loved",
+            Tapback::Liked => "liked",
+            Tapback::Disliked => "disliked",
+            Tapback::Laughed => "laughed at",
+            Tapback::Emphasized => "emphasized",
+            Tapback::Questioned => "questioned",
+            Tapback::Reacted => "reacted to",
+        };
+        f.write_str(verb)
+    }
+}
+
+/// A message with its reactions attached.
+#[derive(Debug)]
+struct ChatMessage {
+    sender: String,
+    chat_name: String,
+    text: Option<String>,
+    sent_at: Option<DateTime<Local>>,
+    unread: bool,
+    edited: bool,
+    unsent: bool,
+    /// Not itself in the requested range; shown because it got a reaction.
+    earlier: bool,
+    reactions: Vec<(Tapback, String)>,
+}
+
+impl ChatMessage {
+    fn from_row(row: &MessageRow, earlier: bool) -> Self {
+        let unsent = row.date_retracted > 0;
+        Self {
+            sender: if row.is_from_me != 0 {
+                "me".to_string()
+            } else {
+                row.sender.clone()
+            },
+            chat_name: row.chat_name.clone(),
+            text: if unsent { None } else { row.body_text() },
+            sent_at: apple_time(row.date),
+            unread: row.is_from_me == 0 && row.is_read == 0,
+            edited: row.date_edited > 0 && !unsent,
+            unsent,
+            earlier,
+            reactions: Vec::new(),
+        }
+    }
+}
+
+/// Turn rows into messages, attaching each tapback to the message it
+/// reacts to (from `rows` or `targets`) instead of listing it separately.
+fn assemble_messages(rows: Vec<MessageRow>, targets: Vec<MessageRow>) -> Vec<ChatMessage> {
+    let (mut tapbacks, rows): (Vec<MessageRow>, Vec<MessageRow>) =
+        rows.into_iter().partition(|r| r.tapback().is_some());
+    tapbacks.sort_by_key(|r| r.rowid);
+
+    let mut guids: Vec<String> = Vec::new();
+    let mut messages: Vec<ChatMessage> = Vec::new();
+    for row in &rows {
+        guids.push(row.guid.clone());
+        messages.push(ChatMessage::from_row(row, false));
+    }
+    for row in &targets {
+        if !guids.contains(&row.guid) {
+            guids.push(row.guid.clone());
+            messages.push(ChatMessage::from_row(row, true));
+        }
+    }
+
+    for tapback in &tapbacks {
+        let (Some((kind, added)), Some(target)) = (tapback.tapback(), tapback.target_guid()) else {
+            continue;
+        };
+        let Some(index) = guids.iter().position(|g| g == target) else {
+            continue;
+        };
+        let who = if tapback.is_from_me != 0 {
+            "me".to_string()
+        } else {
+            tapback.sender.clone()
+        };
+        let reactions = &mut messages[index].reactions;
+        reactions.retain(|(k, w)| !(w == &who && (added || *k == kind)));
+        if added {
+            reactions.push((kind, who));
+        }
+    }
+
+    // Earlier messages only matter for the reactions they received.
+    messages.retain(|m| !m.earlier || !m.reactions.is_empty());
+    messages.sort_by_key(|m| m.sent_at);
+    messages
+}
+
+fn format_messages(messages: &[ChatMessage]) -> String {
+    let mut output = String::new();
+    for msg in messages {
+        let mut header = format!("From: {}", msg.sender);
+        if !msg.chat_name.is_empty() {
+            header.push_str(&format!(" in \"{}\"", msg.chat_name));
+        }
+        if let Some(at) = msg.sent_at {
+            header.push_str(&format!(" · {}", at.format("%Y-%m-%d %H:%M")));
+        }
+        if msg.unread {
+            header.push_str(" · unread");
+        }
+        if msg.earlier {
+            header.push_str(" · earlier message");
+        }
+        output.push_str(&header);
+        output.push('\n');
+        let text = if msg.unsent {
+            "(unsent)".to_string()
+        } else {
+            let text = msg.text.as_deref().unwrap_or("(no text)");
+            if msg.edited {
+                format!("{} (edited)", text)
+            } else {
+                text.to_string()
             }
+        };
+        output.push_str(&format!("Text: {}\n", text));
+        if !msg.reactions.is_empty() {
+            let reactions: Vec<String> = msg
+                .reactions
+                .iter()
+                .map(|(kind, who)| format!("{} {}", who, kind))
+                .collect();
+            output.push_str(&format!("Reactions: {}\n", reactions.join(", ")));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// A conversation in the `threads` listing.
+#[derive(Debug, Serialize)]
+struct ThreadSummary {
+    chat: String,
+    name: String,
+    unread: u64,
+    last_sender: String,
+    last_message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_at: Option<DateTime<Local>>,
+}
+
+impl std::fmt::Display for ThreadSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if self.unread > 0 {
+            write!(f, " — {} unread", self.unread)?;
+        }
+        if let Some(at) = self.last_at {
+            write!(f, " · {}", at.format("%Y-%m-%d %H:%M"))?;
+        }
+        writeln!(f)?;
+        writeln!(f, "  {}: {}", self.last_sender, self.last_message)
+    }
+}
+
+/// The Messages database, read through the `sqlite3` command.
+struct MessagesDb {
+    path: PathBuf,
+}
+
+impl MessagesDb {
+    /// Locate the database and check it can be read.
+    fn open() -> Result<Self, ToolError> {
+        let home = std::env::var("HOME").map_err(|_| ToolError::ExecutionFailed {
+            name: "imessage_read".into(),
+            message: "HOME not set".into(),
         })?;
+        let path = PathBuf::from(home).join("Library/Messages/chat.db");
+        match std::fs::File::open(&path) {
+            Ok(_) => Ok(Self { path }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(ToolError::failed(
+                "imessage_read",
+                format!("Messages database not found at {}", path.display()),
+                ToolFailure::new(ToolErrorKind::NotFound)
+                    .with_remediation("Sign in to Messages on this Mac first."),
+            )),
+            Err(e) => Err(full_disk_access_error(&e.to_string())),
+        }
+    }
 
-        if messages.is_empty() {
-            return Ok(ToolOutput::text(format!(
-                "No incoming messages in the last {} minute(s).",
-                minutes
-            )));
+    async fn query(&self, sql: &str) -> Result<Vec<serde_json::Value>, ToolError> {
+        let output = tokio::process::Command::new("sqlite3")
+            .arg("-readonly")
+            .arg("-json")
+            .arg(&self.path)
+            .arg(sql)
+            .output()
+            .await
+            .map_err(|e| ToolError::ExecutionFailed {
+                name: "imessage_read".into(),
+                message: format!("Failed to run sqlite3: {e}"),
+            })?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if is_access_denied(&stderr) {
+                return Err(full_disk_access_error(stderr.trim()));
+            }
+            return Err(ToolError::ExecutionFailed {
+                name: "imessage_read".into(),
+                message: format!("Cannot read Messages database: {}", stderr.trim()),
+            });
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.trim().is_empty() {
+            return Ok(Vec::new());
         }
+        serde_json::from_str(&stdout).map_err(|e| ToolError::ExecutionFailed {
+            name: "imessage_read".into(),
+            message: format!("JSON parse error: {e}"),
+        })
+    }
 
-        let mut output = format!(
-            "Recent iMessages (last {} minute(s), {} message(s)):\n\n",
-            minutes,
-            messages.len()
+    /// Columns that only newer macOS versions have, or `0` in their place.
+    async fn optional_columns(&self) -> Result<(String, String), ToolError> {
+        let columns: Vec<String> = self
+            .query("SELECT name FROM pragma_table_info('message');")
+            .await?
+            .iter()
+            .filter_map(|r| r["name"].as_str().map(str::to_string))
+            .collect();
+        let column = |name: &str| {
+            if columns.iter().any(|c| c == name) {
+                format!("COALESCE(m.{}, 0)", name)
+            } else {
+                "0".to_string()
+            }
+        };
+        Ok((column("date_edited"), column("date_retracted")))
+    }
+
+    /// Message rows matching `filter`, newest first (or oldest first with
+    /// `ascending`, for incremental reads).
+    async fn rows(
+        &self,
+        filter: &str,
+        chat: Option<&str>,
+        limit: u64,
+        ascending: bool,
+    ) -> Result<Vec<MessageRow>, ToolError> {
+        let (edited, retracted) = self.optional_columns().await?;
+        let chat_filter = chat.map(chat_condition).unwrap_or_default();
+        let sql = format!(
+            "SELECT m.ROWID AS rowid, m.guid AS guid, m.text AS text, \
+             hex(m.attributedBody) AS body, m.is_from_me AS is_from_me, \
+             COALESCE(m.is_read, 0) AS is_read, COALESCE(m.date, 0) AS date, \
+             {edited} AS date_edited, {retracted} AS date_retracted, \
+             m.associated_message_guid AS assoc_guid, \
+             COALESCE(m.associated_message_type, 0) AS assoc_type, \
+             COALESCE(h.id, '') AS sender, COALESCE(c.guid, '') AS chat, \
+             COALESCE(c.display_name, '') AS chat_name \
+             FROM message m \
+             JOIN chat_message_join cmj ON cmj.message_id = m.ROWID \
+             JOIN chat c ON c.ROWID = cmj.chat_id \
+             LEFT JOIN handle h ON m.handle_id = h.ROWID \
+             WHERE {filter}{chat_filter} \
+             ORDER BY m.ROWID {order} LIMIT {limit};",
+            order = if ascending { "ASC" } else { "DESC" },
         );
-        for msg in &messages {
-            output.push_str(&format!("From: {}\n", msg.sender));
-            output.push_str(&format!("Text: {}\n\n", msg.text));
-        }
+        Ok(self
+            .query(&sql)
+            .await?
+            .into_iter()
+            .filter_map(|r| serde_json::from_value(r).ok())
+            .collect())
+    }
 
-        Ok(ToolOutput::text(output))
+    /// Messages matching `filter`, with tapbacks attached.
+    async fn messages(
+        &self,
+        filter: &str,
+        chat: Option<&str>,
+        limit: u64,
+        ascending: bool,
+    ) -> Result<Vec<ChatMessage>, ToolError> {
+        let rows = self.rows(filter, chat, limit, ascending).await?;
+        self.assemble(rows).await
     }
 
-    fn risk_level(&self) -> RiskLevel {
-        RiskLevel::ReadOnly
+    /// Attach tapbacks to their messages, reading reacted-to messages that
+    /// are not among `rows`.
+    async fn assemble(&self, rows: Vec<MessageRow>) -> Result<Vec<ChatMessage>, ToolError> {
+        let known: Vec<&str> = rows.iter().map(|r| r.guid.as_str()).collect();
+        let missing: Vec<String> = rows
+            .iter()
+            .filter_map(|r| r.tapback().and(r.target_guid()))
+            .filter(|g| !known.contains(g))
+            .map(sql_string)
+            .collect();
+        let targets = if missing.is_empty() {
+            Vec::new()
+        } else {
+            self.rows(
+                &format!("m.guid IN ({})", missing.join(", ")),
+                None,
+                missing.len() as u64,
+                false,
+            )
+            .await?
+        };
+        Ok(assemble_messages(rows, targets))
     }
 
-    fn timeout(&self) -> Duration {
-        Duration::from_secs(15)
+    async fn latest_rowid(&self) -> Result<i64, ToolError> {
+        Ok(self
+            .query("SELECT COALESCE(MAX(ROWID), 0) AS rowid FROM message;")
+            .await?
+            .first()
+            .and_then(|r| r["rowid"].as_i64())
+            .unwrap_or(0))
     }
+
+    /// Conversations active in the last `days`, unread ones first.
+    async fn threads(
+        &self,
+        days: u64,
+        chat: Option<&str>,
+        limit: u64,
+    ) -> Result<Vec<ThreadSummary>, ToolError> {
+        let chat_filter = chat.map(chat_condition).unwrap_or_default();
+        let sql = format!(
+            "SELECT c.guid AS chat, \
+             COALESCE(NULLIF(c.display_name, ''), c.chat_identifier, c.guid) AS name, \
+             SUM(CASE WHEN m.is_from_me = 0 AND m.is_read = 0 \
+                 AND COALESCE(m.associated_message_type, 0) = 0 THEN 1 ELSE 0 END) AS unread, \
+             MAX(m.ROWID) AS last_rowid \
+             FROM chat c \
+             JOIN chat_message_join cmj ON cmj.chat_id = c.ROWID \
+             JOIN message m ON m.ROWID = cmj.message_id \
+             WHERE {since}{chat_filter} \
+             GROUP BY c.ROWID \
+             ORDER BY (unread > 0) DESC, last_rowid DESC LIMIT {limit};",
+            since = since_minutes(days * 24 * 60),
+        );
+        let summaries = self.query(&sql).await?;
+        let last_ids: Vec<String> = summaries
+            .iter()
+            .filter_map(|r| r["last_rowid"].as_i64())
+            .map(|id| id.to_string())
+            .collect();
+        let last_rows = if last_ids.is_empty() {
+            Vec::new()
+        } else {
+            self.rows(
+                &format!("m.ROWID IN ({})", last_ids.join(", ")),
+                None,
+                last_ids.len() as u64,
+                false,
+            )
+            .await?
+        };
+        Ok(summaries
+            .iter()
+            .map(|r| {
+                let last = last_rows
+                    .iter()
+                    .find(|row| Some(row.rowid) == r["last_rowid"].as_i64());
+                let message = last.map(|row| ChatMessage::from_row(row, false));
+                let preview = match &message {
+                    Some(m) if m.unsent => "(unsent)".to_string(),
+                    Some(m) => truncate_preview(m.text.as_deref().unwrap_or("(no text)")),
+                    None => String::new(),
+                };
+                ThreadSummary {
+                    chat: r["chat"].as_str().unwrap_or("").to_string(),
+                    name: r["name"].as_str().unwrap_or("").to_string(),
+                    unread: r["unread"].as_u64().unwrap_or(0),
+                    last_sender: message
+                        .as_ref()
+                        .map(|m| m.sender.clone())
+                        .unwrap_or_default(),
+                    last_message: preview,
+                    last_at: message.and_then(|m| m.sent_at),
+                }
+            })
+            .collect())
+    }
+}
+
+/// SQL condition on `m.date` for the last `minutes`.
+fn since_minutes(minutes: u64) -> String {
+    format!(
+        "m.date > (strftime('%s', 'now') - {} - {}) * 1000000000",
+        APPLE_EPOCH_OFFSET,
+        minutes * 60
+    )
+}
+
+/// SQL condition matching a chat by handle, group name or GUID.
+fn chat_condition(chat: &str) -> String {
+    let value = sql_string(chat.trim());
+    format!(" AND (c.chat_identifier = {value} OR c.guid = {value} OR c.display_name = {value})")
+}
+
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Convert a Messages timestamp — nanoseconds (or, in old databases,
+/// seconds) since 2001-01-01 — to local time.
+fn apple_time(date: i64) -> Option<DateTime<Local>> {
+    if date <= 0 {
+        return None;
+    }
+    let secs = if date > 1_000_000_000_000 {
+        date / 1_000_000_000
+    } else {
+        date
+    };
+    DateTime::from_timestamp(secs + APPLE_EPOCH_OFFSET, 0).map(|t| t.with_timezone(&Local))
+}
+
+fn truncate_preview(text: &str) -> String {
+    let line = text.lines().next().unwrap_or("").trim();
+    if line.chars().count() > PREVIEW_CHARS {
+        let cut: String = line.chars().take(PREVIEW_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+fn is_access_denied(stderr: &str) -> bool {
+    let lower = stderr.to_lowercase();
+    [
+        "unable to open",
+        "authorization denied",
+        "not authorized",
+        "operation not permitted",
+    ]
+    .iter()
+    .any(|n| lower.contains(n))
+}
+
+fn full_disk_access_error(detail: &str) -> ToolError {
+    ToolError::failed(
+        "imessage_read",
+        format!("Cannot read the Messages database: {}", detail),
+        ToolFailure::new(ToolErrorKind::PermissionDenied).with_remediation(
+            "Grant Full Disk Access to the terminal running Rustant in System Settings → \
+             Privacy & Security → Full Disk Access, then restart the terminal.",
+        ),
+    )
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.is_empty() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Extract the text of an `attributedBody` blob: an `NSAttributedString`
+/// serialized with `NSArchiver` (typedstream), which newer macOS versions
+/// store instead of filling in `text`.
+///
+/// The string follows the `NSString` class name as a `+` marker and a
+/// length: one byte, or `0x81` followed by a little-endian `u16`, or `0x82`
+/// followed by a little-endian `u32`.
+fn decode_attributed_body(blob: &[u8]) -> Option<String> {
+    let class = b"NSString";
+    let start = blob.windows(class.len()).position(|w| w == class)? + class.len();
+    let rest = &blob[start..];
+    let marker = rest.iter().position(|&b| b == b'+')?;
+    let rest = &rest[marker + 1..];
+    let (len, offset) = match *rest.first()? {
+        0x81 => (
+            u16::from_le_bytes([*rest.get(1)?, *rest.get(2)?]) as usize,
+            3,
+        ),
+        0x82 => (
+            u32::from_le_bytes([*rest.get(1)?, *rest.get(2)?, *rest.get(3)?, *rest.get(4)?])
+                as usize,
+            5,
+        ),
+        n => (n as usize, 1),
+    };
+    let bytes = rest.get(offset..offset + len)?;
+    let text = String::from_utf8_lossy(bytes).trim().to_string();
+    (!text.is_empty()).then_some(text)
 }
 
 // ── AppleScript helpers ────────────────────────────────────────────────────
@@ -256,13 +1018,6 @@ struct ContactResult {
     email: Option<String>,
 }
 
-/// An incoming iMessage from the database.
-#[derive(Debug)]
-struct IncomingMessage {
-    sender: String,
-    text: String,
-}
-
 /// Search macOS Contacts via AppleScript.
 async fn search_contacts_applescript(query: &str) -> Result<Vec<ContactResult>, String> {
     let escaped = query.replace('"', "\\\"");
@@ -362,66 +1117,6 @@ async fn send_imessage_applescript(recipient: &str, text: &str) -> Result<(), St
     Ok(())
 }
 
-/// Read recent incoming iMessages using sqlite3 on the Messages database.
-async fn read_recent_imessages(minutes: u64, limit: u64) -> Result<Vec<IncomingMessage>, String> {
-    let home = std::env::var("HOME").map_err(|_| "HOME not set".to_string())?;
-    let db_path = format!("{}/Library/Messages/chat.db", home);
-
-    // macOS Messages uses Apple's Core Data epoch (2001-01-01) in nanoseconds.
-    // We compute seconds-ago × 1e9 for the date comparison.
-    let seconds = minutes * 60;
-    let query = format!(
-        "SELECT m.text, h.id as sender \
-         FROM message m \
-         JOIN handle h ON m.handle_id = h.ROWID \
-         WHERE m.is_from_me = 0 \
-         AND m.text IS NOT NULL \
-         AND m.date > (strftime('%s', 'now') - 978307200 - {seconds}) * 1000000000 \
-         ORDER BY m.date DESC \
-         LIMIT {limit};",
-        seconds = seconds,
-        limit = limit,
-    );
-
-    let output = tokio::process::Command::new("sqlite3")
-        .args([&db_path, "-json", &query])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to read Messages DB: {e}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        // Full Disk Access may be needed
-        return Err(format!(
-            "Cannot read Messages database: {}. \
-             Ensure your terminal has Full Disk Access in System Settings.",
-            stderr
-        ));
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    if stdout.trim().is_empty() {
-        return Ok(vec![]);
-    }
-
-    let rows: Vec<serde_json::Value> =
-        serde_json::from_str(&stdout).map_err(|e| format!("JSON parse error: {e}"))?;
-
-    let messages = rows
-        .iter()
-        .filter_map(|r| {
-            let sender = r["sender"].as_str()?.to_string();
-            let text = r["text"].as_str().unwrap_or("").to_string();
-            if text.is_empty() {
-                return None;
-            }
-            Some(IncomingMessage { sender, text })
-        })
-        .collect();
-
-    Ok(messages)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -447,7 +1142,7 @@ mod tests {
 
     #[test]
     fn test_imessage_read_tool_definition() {
-        let tool = IMessageReadTool;
+        let tool = IMessageReadTool::new(PathBuf::from("/tmp"));
         assert_eq!(tool.name(), "imessage_read");
         assert_eq!(tool.risk_level(), RiskLevel::ReadOnly);
         let schema = tool.parameters_schema();
@@ -513,7 +1208,7 @@ mod tests {
 
     #[test]
     fn test_imessage_read_tool_timeout() {
-        let tool = IMessageReadTool;
+        let tool = IMessageReadTool::new(PathBuf::from("/tmp"));
         assert_eq!(tool.timeout(), Duration::from_secs(15));
     }
 
@@ -538,7 +1233,7 @@ mod tests {
 
     #[test]
     fn test_imessage_read_no_required_fields() {
-        let tool = IMessageReadTool;
+        let tool = IMessageReadTool::new(PathBuf::from("/tmp"));
         let schema = tool.parameters_schema();
         // read tool has no required fields (minutes and limit are optional with defaults)
         assert!(schema.get("required").is_none());
@@ -551,6 +1246,137 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn row(rowid: i64, guid: &str, text: &str) -> MessageRow {
+        MessageRow {
+            rowid,
+            guid: guid.into(),
+            text: Some(text.into()),
+            sender: "+15551234567".into(),
+            chat: "iMessage;-;+15551234567".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_imessage_tapbacks_attach_to_target() {
+        let mut like = row(2, "tap-1", "Liked \u{201c}hello\u{201d}");
+        like.assoc_type = 2001;
+        like.assoc_guid = Some("p:0/msg-1".into());
+        let mut unlike = like.clone();
+        unlike.rowid = 3;
+        unlike.assoc_type = 3001;
+        let mut love = like.clone();
+        love.rowid = 4;
+        love.assoc_type = 2000;
+
+        let messages = assemble_messages(vec![row(1, "msg-1", "hello"), like.clone()], vec![]);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            messages[0].reactions,
+            vec![(Tapback::Liked, "+15551234567".into())]
+        );
+
+        let messages =
+            assemble_messages(vec![row(1, "msg-1", "hello"), like, unlike, love], vec![]);
+        assert_eq!(
+            messages[0].reactions,
+            vec![(Tapback::Loved, "+15551234567".into())]
+        );
+    }
+
+    #[test]
+    fn test_imessage_tapback_on_earlier_message() {
+        let mut like = row(5, "tap-1", "Liked");
+        like.assoc_type = 2001;
+        like.assoc_guid = Some("bp:msg-1".into());
+        let messages = assemble_messages(
+            vec![like],
+            vec![row(1, "msg-1", "hello"), row(2, "msg-2", "unrelated")],
+        );
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].earlier);
+        assert_eq!(messages[0].text.as_deref(), Some("hello"));
+    }
+
+    #[test]
+    fn test_imessage_edited_unsent_unread() {
+        let mut edited = row(1, "a", "fixed typo");
+        edited.date_edited = 1;
+        let mut unsent = row(2, "b", "oops");
+        unsent.date_retracted = 1;
+        unsent.is_read = 1;
+        let edited = ChatMessage::from_row(&edited, false);
+        let unsent = ChatMessage::from_row(&unsent, false);
+        assert!(edited.edited && edited.unread);
+        assert!(unsent.unsent && !unsent.edited && !unsent.unread);
+        assert!(unsent.text.is_none());
+        let output = format_messages(&[edited, unsent]);
+        assert!(output.contains("fixed typo (edited)"));
+        assert!(output.contains("(unsent)"));
+        assert!(output.contains("unread"));
+    }
+
+    #[test]
+    fn test_imessage_decode_attributed_body() {
+        let mut blob = b"\x04\x0bstreamtyped\x81\xe8\x03\x84\x01@\x84\x84\x84\x12NSAttributedString\x00\x84\x84\x08NSObject\x00\x85\x92\x84\x84\x84\x08NSString\x01\x94\x84\x01+".to_vec();
+        blob.push(11);
+        blob.extend_from_slice(b"hello world\x86\x84");
+        assert_eq!(
+            decode_attributed_body(&blob).as_deref(),
+            Some("hello world")
+        );
+
+        let long = "x".repeat(300);
+        let mut blob = b"NSString\x01\x94\x84\x01+\x81".to_vec();
+        blob.extend_from_slice(&(300u16).to_le_bytes());
+        blob.extend_from_slice(long.as_bytes());
+        assert_eq!(decode_attributed_body(&blob), Some(long));
+
+        let mut from_hex = row(1, "a", "");
+        from_hex.body = Some("4E53537472696E67012B026869".into());
+        assert_eq!(from_hex.body_text().as_deref(), Some("hi"));
+        assert!(decode_attributed_body(b"no string here").is_none());
+    }
+
+    #[test]
+    fn test_imessage_sync_state_only_new_rows() {
+        let mut sync = SyncState::default();
+        assert_eq!(sync.floor_for(None), None);
+        sync.advance(&[row(10, "a", "x")], None, 12);
+        assert_eq!(sync.floor, Some(12));
+        assert!(!sync.is_new(&row(11, "b", "y")));
+        assert!(sync.is_new(&row(13, "c", "z")));
+
+        sync.advance(&[row(20, "d", "w")], Some("+15551234567"), 25);
+        assert_eq!(sync.floor, Some(12));
+        assert_eq!(sync.floor_for(None), Some(12));
+        assert!(!sync.is_new(&row(15, "e", "v")));
+        let mut other = row(15, "f", "u");
+        other.chat = "iMessage;-;other".into();
+        assert!(sync.is_new(&other));
+    }
+
+    #[test]
+    fn test_imessage_sync_state_persists() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tool = IMessageReadTool::new(dir.path().to_path_buf());
+        let mut sync = tool.load_sync();
+        sync.advance(&[row(7, "a", "x")], None, 9);
+        tool.save_sync(&sync).unwrap();
+        let loaded = tool.load_sync();
+        assert_eq!(loaded.floor, Some(9));
+        assert_eq!(loaded.chats.get("iMessage;-;+15551234567"), Some(&7));
+    }
+
+    #[test]
+    fn test_imessage_access_denied_detection() {
+        assert!(is_access_denied("Error: unable to open database file"));
+        assert!(is_access_denied("authorization denied"));
+        assert!(!is_access_denied("Error: no such column: foo"));
+        assert_eq!(truncate_preview("line one\nline two"), "line one");
+        assert!(truncate_preview(&"a".repeat(200)).ends_with('…'));
+    }
+
     #[tokio::test]
     async fn test_imessage_contacts_null_query() {
         let tool = IMessageContactsTool;
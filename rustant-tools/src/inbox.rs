@@ -1,8 +1,10 @@
 //! Inbox tool — quick capture for tasks, ideas, and notes.
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use rustant_core::error::ToolError;
+use rustant_core::search::parse_query;
+use rustant_core::similarity::{Signature, SimilarityConfig, SimilarityScore, SimilarityService};
 use rustant_core::types::{RiskLevel, ToolOutput};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
@@ -10,6 +12,12 @@ use std::path::PathBuf;
 
 use crate::registry::Tool;
 
+/// How far apart copies of the same notification may arrive and still be
+/// collapsed into one item.
+const DUPLICATE_WINDOW_HOURS: i64 = 24;
+/// Search results per page unless `limit` is given.
+const SEARCH_PAGE_SIZE: usize = 50;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct InboxItem {
     id: usize,
@@ -19,6 +27,25 @@ struct InboxItem {
     created_at: DateTime<Utc>,
     #[serde(default)]
     done: bool,
+    /// Origin of items synced from a CDC source (`<source>/<key>`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    /// Origins of copies from other sources collapsed into this item.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    also_from: Vec<String>,
+    /// Fingerprint of `text` for duplicate detection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<Signature>,
+}
+
+impl InboxItem {
+    fn source_name(&self) -> Option<&str> {
+        self.source.as_deref().and_then(|o| o.split('/').next())
+    }
+
+    fn has_origin(&self, origin: &str) -> bool {
+        self.source.as_deref() == Some(origin) || self.also_from.iter().any(|o| o == origin)
+    }
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -45,7 +72,7 @@ impl InboxTool {
 
     fn load_state(&self) -> InboxState {
         let path = self.state_path();
-        if path.exists() {
+        let mut state: InboxState = if path.exists() {
             std::fs::read_to_string(&path)
                 .ok()
                 .and_then(|s| serde_json::from_str(&s).ok())
@@ -55,7 +82,14 @@ impl InboxTool {
                 items: Vec::new(),
                 next_id: 1,
             }
+        };
+        // Items saved before signatures were stored.
+        for item in &mut state.items {
+            if item.signature.is_none() {
+                item.signature = Some(Signature::of(&item.text));
+            }
         }
+        state
     }
 
     fn save_state(&self, state: &InboxState) -> Result<(), ToolError> {
@@ -83,13 +117,175 @@ impl InboxTool {
     }
 }
 
+/// The open item `text` duplicates, and why.
+///
+/// `source` is the synced source the text comes from; items from that same
+/// source are never matched, since its distinct records are distinct items.
+fn find_duplicate<'a>(
+    state: &'a mut InboxState,
+    text: &str,
+    signature: &Signature,
+    source: Option<&str>,
+) -> Option<(&'a mut InboxItem, SimilarityScore)> {
+    let service = SimilarityService::new(SimilarityConfig::inbox());
+    let since = Utc::now() - Duration::hours(DUPLICATE_WINDOW_HOURS);
+    let candidates = state.items.iter().enumerate().filter_map(|(index, item)| {
+        let same_source = source.is_some_and(|source| {
+            item.source_name() == Some(source)
+                || item
+                    .also_from
+                    .iter()
+                    .any(|o| o.split('/').next() == Some(source))
+        });
+        let open = !item.done && (source.is_none() || item.created_at >= since);
+        let signature = item.signature.as_ref()?;
+        (open && !same_source).then_some((index, item.text.as_str(), signature))
+    });
+    let (index, score) = service.best_match(text, signature, candidates)?;
+    Some((&mut state.items[index], score))
+}
+
+/// Create or update the inbox item synced from a CDC source record.
+///
+/// Items are matched by `<source>/<key>`, so a changed record updates its
+/// item in place. A new record that duplicates an open item from another
+/// source within a day — the same event reported by email and Slack, say —
+/// is collapsed into that item. Returns the item id and whether it was
+/// created.
+pub fn upsert_synced_item(
+    workspace: PathBuf,
+    source: &str,
+    key: &str,
+    text: &str,
+    tags: &[String],
+) -> Result<(usize, bool), ToolError> {
+    let inbox = InboxTool::new(workspace);
+    let mut state = inbox.load_state();
+    let origin = format!("{}/{}", source, key);
+    let signature = Signature::of(text);
+    let existing = state.items.iter().position(|i| i.has_origin(&origin));
+    let (id, created) = if let Some(index) = existing {
+        let item = &mut state.items[index];
+        // A collapsed copy does not overwrite the item's own text.
+        if item.source.as_deref() == Some(origin.as_str()) {
+            item.text = text.to_string();
+            item.tags = tags.to_vec();
+            item.signature = Some(signature);
+        } else {
+            merge_tags(item, tags);
+        }
+        (item.id, false)
+    } else if let Some((item, score)) = find_duplicate(&mut state, text, &signature, Some(source)) {
+        tracing::debug!(
+            item = item.id,
+            origin = %origin,
+            reason = %score,
+            "Collapsed duplicate inbox item"
+        );
+        item.also_from.push(origin);
+        merge_tags(item, tags);
+        (item.id, false)
+    } else {
+        let id = state.next_id.max(1);
+        state.next_id = id + 1;
+        state.items.push(InboxItem {
+            id,
+            text: text.to_string(),
+            tags: tags.to_vec(),
+            created_at: Utc::now(),
+            done: false,
+            source: Some(origin),
+            also_from: Vec::new(),
+            signature: Some(signature),
+        });
+        (id, true)
+    };
+    inbox.save_state(&state)?;
+    Ok((id, created))
+}
+
+/// Item filters for the search action.
+struct SearchFilter {
+    tag: Option<String>,
+    source: Option<String>,
+    done: Option<bool>,
+    since: Option<DateTime<Utc>>,
+}
+
+impl SearchFilter {
+    fn from_args(args: &Value) -> Result<Self, ToolError> {
+        let invalid = |reason: String| ToolError::InvalidArguments {
+            name: "inbox".to_string(),
+            reason,
+        };
+        let text = |key: &str| {
+            args.get(key)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_lowercase)
+        };
+        let done = match text("status").as_deref() {
+            None | Some("all") => None,
+            Some("open") => Some(false),
+            Some("done") => Some(true),
+            Some(other) => {
+                return Err(invalid(format!(
+                    "status must be open, done or all, not '{}'",
+                    other
+                )));
+            }
+        };
+        let since = text("since")
+            .map(|s| {
+                NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+                    .map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+                    .map_err(|_| invalid(format!("since must be a YYYY-MM-DD date, not '{}'", s)))
+            })
+            .transpose()?;
+        Ok(Self {
+            tag: text("tag"),
+            source: text("source"),
+            done,
+            since,
+        })
+    }
+
+    fn matches(&self, item: &InboxItem) -> bool {
+        self.tag
+            .as_ref()
+            .is_none_or(|tag| item.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            && self.source.as_ref().is_none_or(|source| {
+                item.source_name()
+                    .is_some_and(|s| s.eq_ignore_ascii_case(source))
+                    || item.also_from.iter().any(|o| {
+                        o.split('/')
+                            .next()
+                            .is_some_and(|s| s.eq_ignore_ascii_case(source))
+                    })
+            })
+            && self.done.is_none_or(|done| item.done == done)
+            && self.since.is_none_or(|since| item.created_at >= since)
+    }
+}
+
+fn merge_tags(item: &mut InboxItem, tags: &[String]) {
+    for tag in tags {
+        if !item.tags.contains(tag) {
+            item.tags.push(tag.clone());
+        }
+    }
+}
+
 #[async_trait]
 impl Tool for InboxTool {
     fn name(&self) -> &str {
         "inbox"
     }
     fn description(&self) -> &str {
-        "Quick capture inbox for tasks, ideas, and notes. Actions: add, list, search, clear, tag, done."
+        "Quick capture inbox for tasks, ideas, and notes. Actions: add, list, search, clear, tag, done. \
+         search matches words and \"quoted phrases\" and can filter by tag, source, status and since; \
+         use offset to page through results."
     }
     fn parameters_schema(&self) -> Value {
         json!({
@@ -100,9 +296,18 @@ impl Tool for InboxTool {
                     "enum": ["add", "list", "search", "clear", "tag", "done"],
                     "description": "Action to perform"
                 },
-                "text": { "type": "string", "description": "Item text (for add/search)" },
+                "text": { "type": "string", "description": "Item text (for add), or words and \"quoted phrases\" to find (for search)" },
                 "id": { "type": "integer", "description": "Item ID (for tag/done)" },
-                "tag": { "type": "string", "description": "Tag name (for tag action)" }
+                "tag": { "type": "string", "description": "Tag name (for tag action); only items with this tag (for search)" },
+                "source": { "type": "string", "description": "Only items synced from this source, e.g. 'email' or 'slack' (for search)" },
+                "status": {
+                    "type": "string",
+                    "enum": ["open", "done", "all"],
+                    "description": "Which items to search (default: all)"
+                },
+                "since": { "type": "string", "description": "Only items captured on or after this date, YYYY-MM-DD (for search)" },
+                "offset": { "type": "integer", "description": "Matches to skip, for the next page (for search)" },
+                "limit": { "type": "integer", "description": "Matches per page (for search, default: 50)" }
             },
             "required": ["action"]
         })
@@ -125,6 +330,13 @@ impl Tool for InboxTool {
                 if text.is_empty() {
                     return Ok(ToolOutput::text("Please provide text for the inbox item."));
                 }
+                let signature = Signature::of(text);
+                if let Some((item, score)) = find_duplicate(&mut state, text, &signature, None) {
+                    return Ok(ToolOutput::text(format!(
+                        "Already in inbox as #{} — {} ({}).",
+                        item.id, item.text, score
+                    )));
+                }
                 let id = state.next_id;
                 state.next_id += 1;
                 state.items.push(InboxItem {
@@ -133,6 +345,9 @@ impl Tool for InboxTool {
                     tags: Vec::new(),
                     created_at: Utc::now(),
                     done: false,
+                    source: None,
+                    also_from: Vec::new(),
+                    signature: Some(signature),
                 });
                 self.save_state(&state)?;
                 Ok(ToolOutput::text(format!("Added to inbox (#{}).", id)))
@@ -150,7 +365,17 @@ impl Tool for InboxTool {
                         } else {
                             format!(" [{}]", i.tags.join(", "))
                         };
-                        format!("  #{} — {}{}", i.id, i.text, tags)
+                        let also = if i.also_from.is_empty() {
+                            String::new()
+                        } else {
+                            let sources: Vec<&str> = i
+                                .also_from
+                                .iter()
+                                .filter_map(|o| o.split('/').next())
+                                .collect();
+                            format!(" (also via {})", sources.join(", "))
+                        };
+                        format!("  #{} — {}{}{}", i.id, i.text, tags, also)
                     })
                     .collect();
                 Ok(ToolOutput::text(format!(
@@ -160,18 +385,26 @@ impl Tool for InboxTool {
                 )))
             }
             "search" => {
-                let query = args
-                    .get("text")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_lowercase();
-                let matches: Vec<String> = state
+                let raw = args.get("text").and_then(|v| v.as_str()).unwrap_or("");
+                let query = parse_query(raw);
+                let filter = SearchFilter::from_args(&args)?;
+                let offset = args.get("offset").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+                let limit = args
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .map(|l| l as usize)
+                    .unwrap_or(SEARCH_PAGE_SIZE);
+                // Items are stored in id order, so pages never shift.
+                let matching: Vec<&InboxItem> = state
                     .items
                     .iter()
-                    .filter(|i| {
-                        i.text.to_lowercase().contains(&query)
-                            || i.tags.iter().any(|t| t.to_lowercase().contains(&query))
-                    })
+                    .filter(|i| filter.matches(i))
+                    .filter(|i| query.matches(&i.text) || i.tags.iter().any(|t| query.matches(t)))
+                    .collect();
+                let matches: Vec<String> = matching
+                    .iter()
+                    .skip(offset)
+                    .take(limit)
                     .map(|i| {
                         format!(
                             "  #{} — {} {}",
@@ -182,13 +415,20 @@ impl Tool for InboxTool {
                     })
                     .collect();
                 if matches.is_empty() {
-                    Ok(ToolOutput::text(format!("No items matching '{}'.", query)))
+                    Ok(ToolOutput::text(format!("No items matching '{}'.", raw)))
                 } else {
-                    Ok(ToolOutput::text(format!(
-                        "Found {} items:\n{}",
-                        matches.len(),
-                        matches.join("\n")
-                    )))
+                    let mut output =
+                        format!("Found {} items:\n{}", matching.len(), matches.join("\n"));
+                    let end = offset + matches.len();
+                    if end < matching.len() {
+                        output.push_str(&format!(
+                            "\nShowing {}-{}; search again with offset {} for more.",
+                            offset + 1,
+                            end,
+                            end
+                        ));
+                    }
+                    Ok(ToolOutput::text(output))
                 }
             }
             "tag" => {
@@ -275,6 +515,101 @@ mod tests {
         assert!(result.content.contains("Cleared 1"));
     }
 
+    #[tokio::test]
+    async fn test_upsert_synced_item_updates_in_place() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let tags = vec!["support".to_string()];
+
+        let (id, created) =
+            upsert_synced_item(workspace.clone(), "tickets", "42", "Refund: open", &tags).unwrap();
+        assert!(created);
+        let (same, created) =
+            upsert_synced_item(workspace.clone(), "tickets", "42", "Refund: closed", &tags)
+                .unwrap();
+        assert_eq!((same, created), (id, false));
+
+        let tool = InboxTool::new(workspace);
+        let result = tool.execute(json!({"action": "list"})).await.unwrap();
+        assert!(result.content.contains("Refund: closed"));
+        assert!(!result.content.contains("Refund: open"));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_synced_item_collapses_cross_channel_duplicates() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let text = "Deploy failed: payments-api on prod-eu (build 1432)";
+
+        let (id, created) =
+            upsert_synced_item(workspace.clone(), "email", "m-1", text, &[]).unwrap();
+        assert!(created);
+        let slack = "[Slack] deploy failed - payments-api on prod-eu, build 1432";
+        let tags = vec!["ops".to_string()];
+        let (same, created) =
+            upsert_synced_item(workspace.clone(), "slack", "C1/171", slack, &tags).unwrap();
+        assert_eq!((same, created), (id, false));
+        // Updates from the collapsed copy find the same item.
+        let (again, _) =
+            upsert_synced_item(workspace.clone(), "slack", "C1/171", slack, &tags).unwrap();
+        assert_eq!(again, id);
+        // Another build is a different event.
+        let (other, created) = upsert_synced_item(
+            workspace.clone(),
+            "webhook",
+            "w-9",
+            "Deploy failed: payments-api on prod-eu (build 1433)",
+            &[],
+        )
+        .unwrap();
+        assert!(created);
+        assert_ne!(other, id);
+
+        let tool = InboxTool::new(workspace);
+        let result = tool.execute(json!({"action": "list"})).await.unwrap();
+        assert!(result.content.contains("2 items"));
+        assert!(result.content.contains("[ops] (also via slack)"));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_synced_item_keeps_same_source_records_apart() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+
+        let (first, _) =
+            upsert_synced_item(workspace.clone(), "tickets", "1", "Refund request", &[]).unwrap();
+        let (second, created) =
+            upsert_synced_item(workspace.clone(), "tickets", "2", "Refund request", &[]).unwrap();
+        assert!(created);
+        assert_ne!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_inbox_add_reports_duplicate() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let tool = InboxTool::new(workspace);
+
+        tool.execute(json!({"action": "add", "text": "Buy groceries"}))
+            .await
+            .unwrap();
+        let result = tool
+            .execute(json!({"action": "add", "text": "buy  groceries!"}))
+            .await
+            .unwrap();
+        assert!(result.content.contains("Already in inbox as #1"));
+        assert!(result.content.contains("normalized 1.00"));
+
+        tool.execute(json!({"action": "done", "id": 1}))
+            .await
+            .unwrap();
+        let result = tool
+            .execute(json!({"action": "add", "text": "Buy groceries"}))
+            .await
+            .unwrap();
+        assert!(result.content.contains("Added to inbox (#2)"));
+    }
+
     #[tokio::test]
     async fn test_inbox_search() {
         let dir = TempDir::new().unwrap();
@@ -296,6 +631,72 @@ mod tests {
         assert!(!result.content.contains("parser"));
     }
 
+    #[tokio::test]
+    async fn test_inbox_search_phrases_filters_and_pages() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let tool = InboxTool::new(workspace.clone());
+
+        for text in [
+            "Review the parser PR",
+            "Parser review notes",
+            "Review PR for the lexer",
+        ] {
+            tool.execute(json!({"action": "add", "text": text}))
+                .await
+                .unwrap();
+        }
+        tool.execute(json!({"action": "tag", "id": 3, "tag": "work"}))
+            .await
+            .unwrap();
+        tool.execute(json!({"action": "done", "id": 1}))
+            .await
+            .unwrap();
+        upsert_synced_item(workspace, "slack", "C1/9", "Review deploy checklist", &[]).unwrap();
+
+        let result = tool
+            .execute(json!({"action": "search", "text": "\"review PR\""}))
+            .await
+            .unwrap();
+        assert!(
+            result.content.contains("Found 1 items"),
+            "{}",
+            result.content
+        );
+        assert!(result.content.contains("#3"));
+
+        let search = |args: Value| {
+            let tool = &tool;
+            async move { tool.execute(args).await.unwrap().content }
+        };
+        let open = search(json!({"action": "search", "text": "review", "status": "open"})).await;
+        assert!(
+            open.contains("Found 3 items") && !open.contains("#1 "),
+            "{}",
+            open
+        );
+        let tagged = search(json!({"action": "search", "text": "review", "tag": "WORK"})).await;
+        assert!(tagged.contains("Found 1 items") && tagged.contains("#3"));
+        let slack = search(json!({"action": "search", "source": "slack"})).await;
+        assert!(slack.contains("deploy checklist") && slack.contains("Found 1 items"));
+        let future =
+            search(json!({"action": "search", "text": "review", "since": "2999-01-01"})).await;
+        assert!(future.contains("No items matching"));
+
+        let first = search(json!({"action": "search", "text": "review", "limit": 2})).await;
+        assert!(first.contains("#1 ") && first.contains("#2 ") && !first.contains("#3 "));
+        assert!(first.contains("search again with offset 2"));
+        let second =
+            search(json!({"action": "search", "text": "review", "limit": 2, "offset": 2})).await;
+        assert!(second.contains("#3 ") && second.contains("#4 ") && !second.contains("#1 "));
+
+        let err = tool
+            .execute(json!({"action": "search", "status": "pending"}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("status must be"));
+    }
+
     #[tokio::test]
     async fn test_inbox_schema() {
         let dir = TempDir::new().unwrap();
@@ -0,0 +1,540 @@
+//! Incident tool — runbook-driven incident response with a postmortem timeline.
+//!
+//! Runbooks come from `.rustant/runbooks/*.yaml`; incident records are kept
+//! by [`rustant_core::incidents::IncidentStore`]. While an incident is open
+//! the agent adds every tool call to its timeline and requires approval for
+//! destructive actions.
+
+use async_trait::async_trait;
+use rustant_core::channels::{ChannelMessage, ChannelUser};
+use rustant_core::error::ToolError;
+use rustant_core::incidents::{
+    Executor, IncidentError, IncidentRecord, IncidentSeverity, IncidentStore, Runbook, RunbookStep,
+};
+use rustant_core::types::{RiskLevel, ToolOutput};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::registry::Tool;
+
+pub struct IncidentTool {
+    workspace: PathBuf,
+}
+
+impl IncidentTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+
+    fn store(&self) -> IncidentStore {
+        IncidentStore::new(&self.workspace)
+    }
+
+    fn failed(e: IncidentError) -> ToolError {
+        ToolError::ExecutionFailed {
+            name: "incident".to_string(),
+            message: e.to_string(),
+        }
+    }
+
+    fn action_list_runbooks(&self) -> Result<ToolOutput, ToolError> {
+        let runbooks = Runbook::load_all(&self.workspace).map_err(Self::failed)?;
+        if runbooks.is_empty() {
+            return Ok(ToolOutput::text(
+                "No runbooks found. Add YAML runbooks to .rustant/runbooks/.",
+            ));
+        }
+        let mut output = String::from("Runbooks:\n");
+        for runbook in &runbooks {
+            output.push_str(&format!(
+                "  {} ({} steps) — {}\n",
+                runbook.name,
+                runbook.steps.len(),
+                runbook.description
+            ));
+        }
+        Ok(ToolOutput::text(output))
+    }
+
+    async fn action_start(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let Some(title) = args.get("title").and_then(|v| v.as_str()) else {
+            return Ok(ToolOutput::text("Missing required parameter: title"));
+        };
+        let runbook = match args.get("runbook").and_then(|v| v.as_str()) {
+            Some(name) => match Runbook::find(&self.workspace, name) {
+                Ok(runbook) => Some(runbook),
+                Err(e @ IncidentError::RunbookNotFound { .. }) => {
+                    return Ok(ToolOutput::text(e.to_string()));
+                }
+                Err(e) => return Err(Self::failed(e)),
+            },
+            None => None,
+        };
+        let record = match self
+            .store()
+            .start(title, runbook.as_ref().map(|r| r.name.clone()))
+        {
+            Ok(record) => record,
+            Err(e @ IncidentError::AlreadyOpen { .. }) => {
+                return Ok(ToolOutput::text(e.to_string()));
+            }
+            Err(e) => return Err(Self::failed(e)),
+        };
+
+        let mut output = format!("Started incident {}: {}\n", record.id, record.title);
+        if args
+            .get("notify")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            output.push_str(&self.notify(&record).await);
+            output.push('\n');
+        }
+        output.push_str("Destructive actions require approval until the incident is closed.\n");
+        if let Some(step) = runbook.as_ref().and_then(|r| r.steps.first()) {
+            output.push_str(&format!("\nFirst step:\n{}", describe_step(step)));
+        }
+        Ok(ToolOutput::text(output))
+    }
+
+    /// Send the start notification to the `[incidents.notify]` channel.
+    async fn notify(&self, record: &IncidentRecord) -> String {
+        let config =
+            rustant_core::config::load_config(Some(&self.workspace), None).unwrap_or_default();
+        let Some(notify) = config.incidents.and_then(|c| c.notify) else {
+            return "No [incidents.notify] channel configured; nobody was notified.".to_string();
+        };
+        let mut manager =
+            rustant_core::channels::build_channel_manager(&config.channels.unwrap_or_default());
+        let Some(channel_type) = manager.channel_type(&notify.channel) else {
+            return format!("Channel '{}' is not configured.", notify.channel);
+        };
+        let sender = ChannelUser::new("rustant", channel_type).with_name("Rustant");
+        let text = format!("Incident {} started: {}", record.id, record.title);
+        manager.enqueue(
+            notify.channel.clone(),
+            ChannelMessage::text(channel_type, &notify.destination_id, sender, text),
+        );
+        for (name, result) in manager.connect_all().await {
+            if let (true, Err(e)) = (name == notify.channel, result) {
+                return format!("Failed to connect channel '{}': {}", notify.channel, e);
+            }
+        }
+        let results = manager.flush_outgoing().await;
+        manager.disconnect_all().await;
+        match results.into_iter().find_map(|(_, r)| r.err()) {
+            Some(e) => format!("Failed to notify '{}': {}", notify.channel, e),
+            None => format!("Notified channel '{}'.", notify.channel),
+        }
+    }
+
+    fn action_step(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let Some(step_id) = args.get("step_id").and_then(|v| v.as_str()) else {
+            return Ok(ToolOutput::text("Missing required parameter: step_id"));
+        };
+        let executed_by = match args.get("executed_by").and_then(|v| v.as_str()) {
+            None => Executor::Agent,
+            Some(s) => match Executor::from_name(s) {
+                Some(executor) => executor,
+                None => {
+                    return Ok(ToolOutput::text(format!(
+                        "Invalid executed_by '{}'. Use: agent, human_approved, human",
+                        s
+                    )));
+                }
+            },
+        };
+        let output = args.get("output").and_then(|v| v.as_str()).unwrap_or("");
+        let decision = args
+            .get("decision")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        let result = self.store().update_open(|record| {
+            let name = record.runbook.clone().unwrap_or_default();
+            let runbook = Runbook::find(&self.workspace, &name)?;
+            let step = runbook
+                .step(step_id)
+                .ok_or_else(|| IncidentError::UnknownStep {
+                    runbook: name,
+                    step: step_id.to_string(),
+                })?;
+            if let (Some(point), Some(choice)) = (&step.decision, &decision)
+                && !point.options.iter().any(|o| &o.label == choice)
+            {
+                let labels: Vec<&str> = point.options.iter().map(|o| o.label.as_str()).collect();
+                return Ok(Err(format!(
+                    "'{}' is not an option for \"{}\". Options: {}",
+                    choice,
+                    point.question,
+                    labels.join(", ")
+                )));
+            }
+            record.record_step(step, executed_by, output, decision.clone());
+            Ok(Ok(record.next_step(&runbook).cloned()))
+        });
+        match result {
+            Ok((_, Err(message))) => Ok(ToolOutput::text(message)),
+            Ok((record, Ok(next))) => {
+                let mut text = format!("Recorded step '{}' on {}.\n", step_id, record.id);
+                match next {
+                    Some(step) => text.push_str(&format!("\nNext step:\n{}", describe_step(&step))),
+                    None => text.push_str("Runbook complete. Close the incident when resolved."),
+                }
+                Ok(ToolOutput::text(text))
+            }
+            Err(
+                e @ (IncidentError::NoOpenIncident
+                | IncidentError::RunbookNotFound { .. }
+                | IncidentError::UnknownStep { .. }),
+            ) => Ok(ToolOutput::text(e.to_string())),
+            Err(e) => Err(Self::failed(e)),
+        }
+    }
+
+    fn action_note(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let Some(text) = args.get("text").and_then(|v| v.as_str()) else {
+            return Ok(ToolOutput::text("Missing required parameter: text"));
+        };
+        let executed_by = args
+            .get("executed_by")
+            .and_then(|v| v.as_str())
+            .and_then(Executor::from_name)
+            .unwrap_or(Executor::Agent);
+        match self.store().update_open(|record| {
+            record.note(text, executed_by);
+            Ok(())
+        }) {
+            Ok((record, ())) => Ok(ToolOutput::text(format!("Noted on {}.", record.id))),
+            Err(e @ IncidentError::NoOpenIncident) => Ok(ToolOutput::text(e.to_string())),
+            Err(e) => Err(Self::failed(e)),
+        }
+    }
+
+    fn action_status(&self) -> Result<ToolOutput, ToolError> {
+        let Some(record) = self.store().open_incident() else {
+            return Ok(ToolOutput::text("No incident is open."));
+        };
+        let mut output = format!(
+            "Incident {}: {} (open since {})\nSteps executed: {}\nTimeline events: {}\n",
+            record.id,
+            record.title,
+            record.started_at.format("%Y-%m-%d %H:%M UTC"),
+            record.steps.len(),
+            record.timeline.len()
+        );
+        if let Some(name) = &record.runbook {
+            match Runbook::find(&self.workspace, name) {
+                Ok(runbook) => match record.next_step(&runbook) {
+                    Some(step) => {
+                        output.push_str(&format!("\nNext step:\n{}", describe_step(step)))
+                    }
+                    None => output.push_str("\nRunbook complete."),
+                },
+                Err(e) => output.push_str(&format!("\n{}", e)),
+            }
+        }
+        Ok(ToolOutput::text(output))
+    }
+
+    fn action_close(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let severity = args.get("severity").and_then(|v| v.as_str());
+        let parsed = severity.and_then(IncidentSeverity::from_name);
+        if let (Some(s), None) = (severity, parsed) {
+            return Ok(ToolOutput::text(format!(
+                "Invalid severity '{}'. Use: low, medium, high, critical",
+                s
+            )));
+        }
+        let root_cause = args
+            .get("root_cause")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        let resolution = args
+            .get("resolution")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        match self
+            .store()
+            .update_open(|record| record.close(parsed, root_cause, resolution))
+        {
+            Ok((record, ())) => Ok(ToolOutput::text(format!(
+                "Closed incident {} (severity {}). Export the postmortem with action 'export'.",
+                record.id,
+                parsed.expect("close checked severity")
+            ))),
+            Err(e @ IncidentError::MissingCloseFields { .. }) => Ok(ToolOutput::text(format!(
+                "{}. Ask the user for the incident severity (low, medium, high, critical) \
+                 and root cause, then call close again.",
+                e
+            ))),
+            Err(e @ IncidentError::NoOpenIncident) => Ok(ToolOutput::text(e.to_string())),
+            Err(e) => Err(Self::failed(e)),
+        }
+    }
+
+    fn action_export(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let store = self.store();
+        let record = match args.get("id").and_then(|v| v.as_str()) {
+            Some(id) => match store.load(id) {
+                Ok(record) => record,
+                Err(IncidentError::Io { .. }) => {
+                    return Ok(ToolOutput::text(format!("Incident {} not found.", id)));
+                }
+                Err(e) => return Err(Self::failed(e)),
+            },
+            None => match store
+                .open_incident()
+                .or_else(|| store.list().into_iter().next())
+            {
+                Some(record) => record,
+                None => return Ok(ToolOutput::text("No incidents recorded.")),
+            },
+        };
+        let (markdown, json) = store.export(&record).map_err(Self::failed)?;
+        Ok(ToolOutput::text(format!(
+            "Exported {} to {} and {}\n\n{}",
+            record.id,
+            markdown.display(),
+            json.display(),
+            record.to_markdown()
+        )))
+    }
+
+    fn action_list(&self) -> Result<ToolOutput, ToolError> {
+        let records = self.store().list();
+        if records.is_empty() {
+            return Ok(ToolOutput::text("No incidents recorded."));
+        }
+        let mut output = String::from("Incidents:\n");
+        for record in &records {
+            let status = match record.severity {
+                Some(severity) if !record.is_open() => format!("closed, {}", severity),
+                _ => "open".to_string(),
+            };
+            output.push_str(&format!("  {} [{}] {}\n", record.id, status, record.title));
+        }
+        Ok(ToolOutput::text(output))
+    }
+}
+
+fn describe_step(step: &RunbookStep) -> String {
+    let mut text = format!("  {}: {}\n", step.id, step.title);
+    if !step.description.is_empty() {
+        text.push_str(&format!("  {}\n", step.description));
+    }
+    if !step.tools.is_empty() {
+        text.push_str(&format!("  Suggested tools: {}\n", step.tools.join(", ")));
+    }
+    for command in &step.commands {
+        text.push_str(&format!("  $ {}\n", command));
+    }
+    if let Some(point) = &step.decision {
+        let labels: Vec<&str> = point.options.iter().map(|o| o.label.as_str()).collect();
+        text.push_str(&format!(
+            "  Decision: {} [{}]\n",
+            point.question,
+            labels.join(" / ")
+        ));
+    }
+    text
+}
+
+#[async_trait]
+impl Tool for IncidentTool {
+    fn name(&self) -> &str {
+        "incident"
+    }
+
+    fn description(&self) -> &str {
+        "Runbook-driven incident response. Start an incident (optionally from a workspace runbook \
+         and notifying the configured channel), record executed runbook steps and decisions, add \
+         notes, close with severity and root cause, and export a postmortem timeline. Actions: \
+         list_runbooks, start, step, note, status, close, export, list."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list_runbooks", "start", "step", "note", "status", "close", "export", "list"],
+                    "description": "Action to perform"
+                },
+                "title": { "type": "string", "description": "Incident title (for start)" },
+                "runbook": { "type": "string", "description": "Runbook name to follow (for start)" },
+                "notify": { "type": "boolean", "description": "Notify the [incidents.notify] channel (for start)" },
+                "step_id": { "type": "string", "description": "Runbook step carried out (for step)" },
+                "output": { "type": "string", "description": "What the step found or changed (for step)" },
+                "decision": { "type": "string", "description": "Option chosen at the step's decision point (for step)" },
+                "executed_by": {
+                    "type": "string",
+                    "enum": ["agent", "human_approved", "human"],
+                    "description": "Who carried out the step (default: agent)"
+                },
+                "text": { "type": "string", "description": "Timeline note (for note)" },
+                "severity": {
+                    "type": "string",
+                    "enum": ["low", "medium", "high", "critical"],
+                    "description": "Incident severity (required for close)"
+                },
+                "root_cause": { "type": "string", "description": "Root cause (required for close)" },
+                "resolution": { "type": "string", "description": "How it was resolved (for close)" },
+                "id": { "type": "string", "description": "Incident ID (for export; default: open or latest)" }
+            },
+            "required": ["action"]
+        })
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Write
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolOutput, ToolError> {
+        let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("");
+
+        match action {
+            "list_runbooks" => self.action_list_runbooks(),
+            "start" => self.action_start(&args).await,
+            "step" => self.action_step(&args),
+            "note" => self.action_note(&args),
+            "status" => self.action_status(),
+            "close" => self.action_close(&args),
+            "export" => self.action_export(&args),
+            "list" => self.action_list(),
+            _ => Ok(ToolOutput::text(format!(
+                "Unknown action: '{}'. Use: list_runbooks, start, step, note, status, close, export, list",
+                action
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const RUNBOOK: &str = r#"
+name: api-errors
+description: Elevated 5xx rate
+steps:
+  - id: triage
+    title: Check service health
+    tools: [system_monitor]
+    decision:
+      question: Did a deploy just go out?
+      options:
+        - label: "yes"
+          next: rollback
+        - label: "no"
+          next: escalate
+  - id: rollback
+    title: Roll back the deploy
+    commands: ["kubectl rollout undo deploy/api"]
+  - id: escalate
+    title: Page the service owner
+"#;
+
+    fn make_tool() -> (IncidentTool, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let runbooks = dir.path().join(".rustant/runbooks");
+        std::fs::create_dir_all(&runbooks).unwrap();
+        std::fs::write(runbooks.join("api.yaml"), RUNBOOK).unwrap();
+        (IncidentTool::new(dir.path().to_path_buf()), dir)
+    }
+
+    #[tokio::test]
+    async fn test_runbook_guided_incident_and_export() {
+        let (tool, dir) = make_tool();
+        let started = tool
+            .execute(json!({"action": "start", "title": "5xx spike", "runbook": "api-errors"}))
+            .await
+            .unwrap();
+        assert!(
+            started
+                .content
+                .contains("First step:\n  triage: Check service health")
+        );
+
+        let invalid = tool
+            .execute(json!({"action": "step", "step_id": "triage", "decision": "maybe"}))
+            .await
+            .unwrap();
+        assert!(invalid.content.contains("Options: yes, no"));
+
+        let stepped = tool
+            .execute(json!({
+                "action": "step",
+                "step_id": "triage",
+                "output": "deploy 42 at 10:02",
+                "decision": "yes"
+            }))
+            .await
+            .unwrap();
+        assert!(
+            stepped
+                .content
+                .contains("Next step:\n  rollback: Roll back the deploy")
+        );
+        assert!(
+            stepped
+                .content
+                .contains("$ kubectl rollout undo deploy/api")
+        );
+
+        let prompt = tool
+            .execute(json!({"action": "close", "resolution": "rolled back"}))
+            .await
+            .unwrap();
+        assert!(
+            prompt
+                .content
+                .starts_with("Closing an incident requires: severity, root_cause.")
+        );
+        tool.execute(json!({
+            "action": "close",
+            "severity": "high",
+            "root_cause": "bad config in deploy 42"
+        }))
+        .await
+        .unwrap();
+
+        let exported = tool.execute(json!({"action": "export"})).await.unwrap();
+        assert!(exported.content.contains("- **Severity:** high"));
+        assert!(
+            exported
+                .content
+                .contains("| triage: Check service health | agent | yes | deploy 42 at 10:02 |")
+        );
+        let exports = dir.path().join(".rustant/incidents/exports");
+        assert_eq!(std::fs::read_dir(exports).unwrap().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_start_rejects_second_incident_and_unknown_runbook() {
+        let (tool, _dir) = make_tool();
+        let unknown = tool
+            .execute(json!({"action": "start", "title": "x", "runbook": "nope"}))
+            .await
+            .unwrap();
+        assert_eq!(unknown.content, "Unknown runbook 'nope'");
+
+        tool.execute(json!({"action": "start", "title": "DB slow"}))
+            .await
+            .unwrap();
+        let second = tool
+            .execute(json!({"action": "start", "title": "Other"}))
+            .await
+            .unwrap();
+        assert!(second.content.contains("is still open"));
+        let status = tool.execute(json!({"action": "status"})).await.unwrap();
+        assert!(status.content.contains("DB slow"));
+    }
+}
@@ -20,6 +20,7 @@ enum NodeType {
     Dataset,
     Person,
     Organization,
+    Article,
 }
 
 impl NodeType {
@@ -31,6 +32,7 @@ impl NodeType {
             "dataset" => Some(Self::Dataset),
             "person" => Some(Self::Person),
             "organization" => Some(Self::Organization),
+            "article" => Some(Self::Article),
             _ => None,
         }
     }
@@ -43,6 +45,7 @@ impl NodeType {
             Self::Dataset => "Dataset",
             Self::Person => "Person",
             Self::Organization => "Organization",
+            Self::Article => "Article",
         }
     }
 }
@@ -204,6 +207,181 @@ impl KnowledgeGraphTool {
     }
 }
 
+/// A captured web article to record in the graph.
+pub(crate) struct ArticleEntry<'a> {
+    /// Stable node id for the article, so re-captures update it.
+    pub id: &'a str,
+    pub title: &'a str,
+    pub description: &'a str,
+    pub author: Option<&'a str>,
+    pub site: Option<&'a str>,
+    pub tags: &'a [String],
+    pub metadata: HashMap<String, String>,
+    /// Text scanned for names of existing entities to link.
+    pub content: &'a str,
+}
+
+/// Entity ids touched by [`KnowledgeGraphTool::record_article`].
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ArticleLinks {
+    pub article_id: String,
+    /// Nodes created for this capture (article, author, site, topics).
+    pub created: Vec<String>,
+    /// Existing nodes the article was linked to.
+    pub linked: Vec<String>,
+}
+
+/// Existing entities mentioned in an article are linked up to this many.
+const MAX_MENTION_LINKS: usize = 10;
+
+impl KnowledgeGraphTool {
+    /// Create or update the node for a captured article, plus its author
+    /// (Person), site (Organization) and topic (Concept) nodes. Entities are
+    /// matched to existing nodes by name, case-insensitively, before any are
+    /// created, and existing entities named in the article's title or content
+    /// are linked with `RelatedTo` edges.
+    pub(crate) fn record_article(&self, entry: &ArticleEntry) -> Result<ArticleLinks, ToolError> {
+        let mut state = self.load_state();
+        let now = Utc::now();
+        let mut links = ArticleLinks {
+            article_id: entry.id.to_string(),
+            ..Default::default()
+        };
+
+        match state.nodes.iter_mut().find(|n| n.id == entry.id) {
+            Some(node) => {
+                node.name = entry.title.to_string();
+                node.description = entry.description.to_string();
+                node.tags = entry.tags.to_vec();
+                node.metadata.extend(entry.metadata.clone());
+                node.updated_at = now;
+            }
+            None => {
+                state.nodes.push(GraphNode {
+                    id: entry.id.to_string(),
+                    name: entry.title.to_string(),
+                    node_type: NodeType::Article,
+                    description: entry.description.to_string(),
+                    tags: entry.tags.to_vec(),
+                    metadata: entry.metadata.clone(),
+                    created_at: now,
+                    updated_at: now,
+                });
+                links.created.push(entry.id.to_string());
+            }
+        }
+
+        let mut related: Vec<(String, NodeType, RelationshipType)> = Vec::new();
+        if let Some(author) = entry.author {
+            related.push((
+                author.to_string(),
+                NodeType::Person,
+                RelationshipType::AuthoredBy,
+            ));
+        }
+        if let Some(site) = entry.site {
+            related.push((
+                site.to_string(),
+                NodeType::Organization,
+                RelationshipType::RelatedTo,
+            ));
+        }
+        for tag in entry.tags {
+            related.push((tag.clone(), NodeType::Concept, RelationshipType::RelatedTo));
+        }
+
+        for (name, node_type, relationship) in related {
+            let existing = state
+                .nodes
+                .iter()
+                .find(|n| n.id != entry.id && n.name.eq_ignore_ascii_case(&name))
+                .map(|n| n.id.clone());
+            let target_id = match existing {
+                Some(id) => {
+                    links.linked.push(id.clone());
+                    id
+                }
+                None => {
+                    let mut id = Self::slug(&name);
+                    if state.nodes.iter().any(|n| n.id == id) {
+                        id = format!("{}-{}", id, node_type.as_str().to_lowercase());
+                    }
+                    state.nodes.push(GraphNode {
+                        id: id.clone(),
+                        name,
+                        node_type,
+                        description: String::new(),
+                        tags: Vec::new(),
+                        metadata: HashMap::new(),
+                        created_at: now,
+                        updated_at: now,
+                    });
+                    links.created.push(id.clone());
+                    id
+                }
+            };
+            Self::link(&mut state, entry.id, &target_id, relationship, now);
+        }
+
+        // Link existing entities the article talks about.
+        let haystack = format!("{}\n{}", entry.title, entry.content).to_lowercase();
+        let mentioned: Vec<String> = state
+            .nodes
+            .iter()
+            .filter(|n| {
+                n.id != entry.id
+                    && n.node_type != NodeType::Article
+                    && n.name.chars().count() >= 4
+                    && !links.created.contains(&n.id)
+                    && !links.linked.contains(&n.id)
+                    && contains_word(&haystack, &n.name.to_lowercase())
+            })
+            .map(|n| n.id.clone())
+            .take(MAX_MENTION_LINKS)
+            .collect();
+        for id in mentioned {
+            Self::link(&mut state, entry.id, &id, RelationshipType::RelatedTo, now);
+            links.linked.push(id);
+        }
+
+        state.next_auto_id += links.created.len();
+        self.save_state(&state)?;
+        Ok(links)
+    }
+
+    /// Add an edge unless an identical one exists.
+    fn link(
+        state: &mut KnowledgeGraphState,
+        source: &str,
+        target: &str,
+        relationship: RelationshipType,
+        now: DateTime<Utc>,
+    ) {
+        let exists = state.edges.iter().any(|e| {
+            e.source_id == source && e.target_id == target && e.relationship_type == relationship
+        });
+        if !exists {
+            state.edges.push(Edge {
+                source_id: source.to_string(),
+                target_id: target.to_string(),
+                relationship_type: relationship,
+                strength: 0.5,
+                notes: String::new(),
+                created_at: now,
+            });
+        }
+    }
+}
+
+/// Whether `needle` occurs in `haystack` with no letter or digit on either side.
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
 #[async_trait]
 impl Tool for KnowledgeGraphTool {
     fn name(&self) -> &str {
@@ -231,7 +409,7 @@ impl Tool for KnowledgeGraphTool {
                 "name": { "type": "string", "description": "Node name" },
                 "node_type": {
                     "type": "string",
-                    "enum": ["paper", "concept", "method", "dataset", "person", "organization"],
+                    "enum": ["paper", "concept", "method", "dataset", "person", "organization", "article"],
                     "description": "Type of node"
                 },
                 "description": { "type": "string", "description": "Node description" },
@@ -268,7 +446,7 @@ impl Tool for KnowledgeGraphTool {
                 "arxiv_id": { "type": "string", "description": "ArXiv paper ID for import" },
                 "filter_type": {
                     "type": "string",
-                    "enum": ["paper", "concept", "method", "dataset", "person", "organization"],
+                    "enum": ["paper", "concept", "method", "dataset", "person", "organization", "article"],
                     "description": "Filter by node type"
                 }
             },
@@ -304,7 +482,7 @@ impl Tool for KnowledgeGraphTool {
                     Some(nt) => nt,
                     None => {
                         return Ok(ToolOutput::text(format!(
-                            "Invalid node_type '{}'. Use: paper, concept, method, dataset, person, organization.",
+                            "Invalid node_type '{}'. Use: paper, concept, method, dataset, person, organization, article.",
                             node_type_str
                         )));
                     }
@@ -7,10 +7,12 @@
 pub mod accessibility;
 pub mod arxiv;
 pub mod arxiv_api;
+pub mod briefing;
 pub mod browser;
 pub mod canvas;
 pub mod career_intel;
 pub mod checkpoint;
+pub mod citation_archive;
 pub mod code_intelligence;
 pub mod codebase_search;
 pub mod compress;
@@ -21,6 +23,7 @@ pub mod experiment_tracker;
 pub mod contacts;
 #[cfg(target_os = "macos")]
 pub mod daily_briefing;
+pub mod diff_summary;
 pub mod file;
 pub mod file_organizer;
 pub mod finance;
@@ -33,18 +36,23 @@ pub mod homekit;
 pub mod http_api;
 pub mod imessage;
 pub mod inbox;
+pub mod incident;
 pub mod knowledge_graph;
 pub mod life_planner;
 pub mod lsp;
 #[cfg(target_os = "macos")]
 pub mod macos;
+pub mod mail_triage;
 #[cfg(target_os = "macos")]
 pub mod meeting;
+pub mod model_registry;
+pub mod paper_sources;
 pub mod pdf_generate;
 #[cfg(target_os = "macos")]
 pub mod photos;
 pub mod pomodoro;
 pub mod privacy_manager;
+pub mod reader_capture;
 pub mod registry;
 pub mod relationships;
 #[cfg(target_os = "macos")]
@@ -57,6 +65,7 @@ pub mod shell;
 pub mod skill_tracker;
 pub mod slack;
 pub mod smart_edit;
+pub mod supply_chain;
 pub mod system_monitor;
 pub mod template;
 pub mod travel;
@@ -64,6 +73,8 @@ pub mod utils;
 #[cfg(target_os = "macos")]
 pub mod voice_tool;
 pub mod web;
+pub mod web_extract;
+pub mod web_politeness;
 
 use registry::{Tool, ToolRegistry};
 use rustant_core::types::ProgressUpdate;
@@ -82,11 +93,16 @@ pub fn register_builtin_tools_with_progress(
     workspace: PathBuf,
     progress_tx: Option<mpsc::UnboundedSender<ProgressUpdate>>,
 ) {
-    let shell_tool: Arc<dyn Tool> = if let Some(tx) = progress_tx {
+    let shell_tool: Arc<dyn Tool> = if let Some(tx) = progress_tx.clone() {
         Arc::new(shell::ShellExecTool::with_progress(workspace.clone(), tx))
     } else {
         Arc::new(shell::ShellExecTool::new(workspace.clone()))
     };
+    let compress_tool: Arc<dyn Tool> = if let Some(tx) = progress_tx.clone() {
+        Arc::new(compress::CompressTool::with_progress(workspace.clone(), tx))
+    } else {
+        Arc::new(compress::CompressTool::new(workspace.clone()))
+    };
 
     #[allow(unused_mut)]
     let mut tools: Vec<Arc<dyn Tool>> = vec![
@@ -112,7 +128,7 @@ pub fn register_builtin_tools_with_progress(
         Arc::new(codebase_search::CodebaseSearchTool::new(workspace.clone())),
         // Cross-platform utility tools
         Arc::new(file_organizer::FileOrganizerTool::new(workspace.clone())),
-        Arc::new(compress::CompressTool::new(workspace.clone())),
+        compress_tool,
         Arc::new(http_api::HttpApiTool::new()),
         Arc::new(template::TemplateTool::new(workspace.clone())),
         // PDF generation
@@ -120,6 +136,7 @@ pub fn register_builtin_tools_with_progress(
         // Personal productivity tools
         Arc::new(pomodoro::PomodoroTool::new(workspace.clone())),
         Arc::new(inbox::InboxTool::new(workspace.clone())),
+        Arc::new(briefing::BriefingTool::new(workspace.clone())),
         Arc::new(relationships::RelationshipsTool::new(workspace.clone())),
         // Life planner — energy-aware scheduling, deadlines, habits
         Arc::new(life_planner::LifePlannerTool::new(workspace.clone())),
@@ -131,21 +148,27 @@ pub fn register_builtin_tools_with_progress(
         Arc::new(career_intel::CareerIntelTool::new(workspace.clone())),
         // Research tools
         Arc::new(arxiv::ArxivResearchTool::new(workspace.clone())),
+        Arc::new(citation_archive::CitationArchiveTool::new(
+            workspace.clone(),
+        )),
         // Cognitive extension tools
         Arc::new(knowledge_graph::KnowledgeGraphTool::new(workspace.clone())),
         Arc::new(experiment_tracker::ExperimentTrackerTool::new(
             workspace.clone(),
         )),
+        Arc::new(model_registry::ModelRegistryTool::new(workspace.clone())),
         Arc::new(code_intelligence::CodeIntelligenceTool::new(
             workspace.clone(),
         )),
         Arc::new(content_engine::ContentEngineTool::new(workspace.clone())),
         Arc::new(skill_tracker::SkillTrackerTool::new(workspace.clone())),
         Arc::new(system_monitor::SystemMonitorTool::new(workspace.clone())),
+        Arc::new(incident::IncidentTool::new(workspace.clone())),
         Arc::new(privacy_manager::PrivacyManagerTool::new(workspace.clone())),
         Arc::new(self_improvement::SelfImprovementTool::new(
             workspace.clone(),
         )),
+        Arc::new(supply_chain::SupplyChainCheckTool::new(workspace.clone())),
         // Slack tool — cross-platform, uses Slack Bot Token API
         Arc::new(slack::SlackTool::new(workspace.clone())),
     ];
@@ -155,7 +178,7 @@ pub fn register_builtin_tools_with_progress(
     {
         tools.push(Arc::new(imessage::IMessageContactsTool));
         tools.push(Arc::new(imessage::IMessageSendTool));
-        tools.push(Arc::new(imessage::IMessageReadTool));
+        tools.push(Arc::new(imessage::IMessageReadTool::new(workspace.clone())));
     }
 
     // macOS native tools — Calendar, Reminders, Notes, App Control, etc.
@@ -166,22 +189,29 @@ pub fn register_builtin_tools_with_progress(
         tools.push(Arc::new(macos::MacosNotesTool));
         tools.push(Arc::new(macos::MacosAppControlTool));
         tools.push(Arc::new(macos::MacosNotificationTool));
-        tools.push(Arc::new(macos::MacosClipboardTool));
+        tools.push(Arc::new(macos::MacosClipboardTool::new(workspace.clone())));
         tools.push(Arc::new(macos::MacosScreenshotTool));
         tools.push(Arc::new(macos::MacosSystemInfoTool));
         tools.push(Arc::new(macos::MacosSpotlightTool));
         tools.push(Arc::new(macos::MacosFinderTool));
         tools.push(Arc::new(macos::MacosFocusModeTool));
-        tools.push(Arc::new(macos::MacosMailTool));
+        tools.push(Arc::new(match progress_tx.clone() {
+            Some(tx) => macos::MacosMailTool::with_progress(workspace.clone(), tx),
+            None => macos::MacosMailTool::new(workspace.clone()),
+        }));
         tools.push(Arc::new(macos::MacosMusicTool));
         tools.push(Arc::new(macos::MacosShortcutsTool));
         tools.push(Arc::new(meeting::MacosMeetingRecorderTool));
-        tools.push(Arc::new(daily_briefing::MacosDailyBriefingTool));
+        tools.push(Arc::new(daily_briefing::MacosDailyBriefingTool::new(
+            workspace.clone(),
+        )));
         tools.push(Arc::new(gui_scripting::MacosGuiScriptingTool));
         tools.push(Arc::new(accessibility::MacosAccessibilityTool));
         tools.push(Arc::new(screen_analyze::MacosScreenAnalyzeTool));
-        tools.push(Arc::new(contacts::MacosContactsTool));
-        tools.push(Arc::new(safari::MacosSafariTool));
+        tools.push(Arc::new(contacts::MacosContactsTool::new(
+            workspace.clone(),
+        )));
+        tools.push(Arc::new(safari::MacosSafariTool::new(workspace.clone())));
         tools.push(Arc::new(voice_tool::MacosSayTool::new()));
         tools.push(Arc::new(photos::MacosPhotosTool::new()));
         tools.push(Arc::new(homekit::HomeKitTool::new()));
@@ -221,11 +251,11 @@ mod tests {
         let mut registry = ToolRegistry::new();
         register_builtin_tools(&mut registry, dir.path().to_path_buf());
 
-        // 40 base + 3 iMessage + 24 macOS native = 67 on macOS
+        // 45 base + 3 iMessage + 24 macOS native = 72 on macOS
         #[cfg(target_os = "macos")]
-        assert_eq!(registry.len(), 67);
+        assert_eq!(registry.len(), 72);
         #[cfg(not(target_os = "macos"))]
-        assert_eq!(registry.len(), 40);
+        assert_eq!(registry.len(), 45);
 
         // Verify all expected tools are registered
         let names = registry.list_names();
@@ -241,6 +271,8 @@ mod tests {
         assert!(names.contains(&"echo".to_string()));
         assert!(names.contains(&"datetime".to_string()));
         assert!(names.contains(&"calculator".to_string()));
+        assert!(names.contains(&"briefing".to_string()));
+        assert!(names.contains(&"supply_chain_check".to_string()));
 
         // iMessage tools on macOS
         #[cfg(target_os = "macos")]
@@ -256,7 +288,7 @@ mod tests {
             assert!(names.contains(&"macos_calendar".to_string()));
             assert!(names.contains(&"macos_reminders".to_string()));
             assert!(names.contains(&"macos_notes".to_string()));
-            assert!(names.contains(&"macos_app_control".to_string()));
+            assert!(names.contains(&"app_control".to_string()));
             assert!(names.contains(&"macos_notification".to_string()));
             assert!(names.contains(&"macos_clipboard".to_string()));
             assert!(names.contains(&"macos_screenshot".to_string()));
@@ -281,6 +313,7 @@ mod tests {
 
         // Research tools
         assert!(names.contains(&"arxiv_research".to_string()));
+        assert!(names.contains(&"citation_archive".to_string()));
 
         // Cognitive extension tools
         assert!(names.contains(&"knowledge_graph".to_string()));
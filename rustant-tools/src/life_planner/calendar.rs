@@ -0,0 +1,441 @@
+//! Calendar busy blocks — from an imported ICS file or, on macOS, Calendar.app.
+//!
+//! Only the busy time matters to the scheduler, so events are reduced to
+//! local start/end pairs. Cancelled, transparent ("free") and all-day events
+//! are ignored. `TZID` parameters are not resolved: such times are read as
+//! local, while `Z` (UTC) times are converted. Daily and weekly `RRULE`s are
+//! expanded; other recurrences count as their first occurrence only.
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use std::path::Path;
+
+/// Occurrences generated per recurring event, at most.
+const MAX_OCCURRENCES: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct BusyBlock {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub title: String,
+}
+
+/// Where the free/busy information for a schedule came from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum SlotSource {
+    Ics,
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    MacosCalendar,
+    /// No calendar available; every work hour is assumed free.
+    Unchecked,
+}
+
+impl SlotSource {
+    /// How a slot from this source is described in a placement's reasoning.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::Ics => "free in ICS calendar",
+            Self::MacosCalendar => "free in Calendar.app",
+            Self::Unchecked => "assumed free (no calendar)",
+        }
+    }
+}
+
+/// Busy blocks for a scheduling window.
+#[derive(Debug)]
+pub(super) struct BusyCalendar {
+    pub blocks: Vec<BusyBlock>,
+    pub source: SlotSource,
+    /// One line on where the blocks came from, or why there are none.
+    pub summary: String,
+}
+
+impl BusyCalendar {
+    fn unchecked(reason: String) -> Self {
+        Self {
+            blocks: Vec::new(),
+            source: SlotSource::Unchecked,
+            summary: reason,
+        }
+    }
+}
+
+/// Load busy blocks overlapping `from..until`.
+///
+/// An imported ICS file wins; otherwise Calendar.app is asked on macOS.
+/// Failures never abort scheduling — they leave the window unchecked and
+/// say why.
+pub(super) async fn load_busy(
+    ics_path: Option<&Path>,
+    from: NaiveDateTime,
+    until: NaiveDateTime,
+) -> BusyCalendar {
+    if let Some(path) = ics_path {
+        return match std::fs::read_to_string(path) {
+            Ok(text) => {
+                let blocks = parse_ics(&text, from, until);
+                BusyCalendar {
+                    summary: format!(
+                        "{} busy block(s) from ICS file {}",
+                        blocks.len(),
+                        path.display()
+                    ),
+                    blocks,
+                    source: SlotSource::Ics,
+                }
+            }
+            Err(e) => BusyCalendar::unchecked(format!(
+                "ICS file {} unreadable ({}); all work hours treated as free",
+                path.display(),
+                e
+            )),
+        };
+    }
+    load_system_calendar(from, until).await
+}
+
+#[cfg(target_os = "macos")]
+async fn load_system_calendar(from: NaiveDateTime, until: NaiveDateTime) -> BusyCalendar {
+    let days = (until - from).num_days().max(1) as u64;
+    match crate::macos::calendar_busy_events(days).await {
+        Ok(events) => {
+            let blocks: Vec<BusyBlock> = events
+                .into_iter()
+                .filter(|(start, end, _)| *end > from && *start < until)
+                .map(|(start, end, title)| BusyBlock { start, end, title })
+                .collect();
+            BusyCalendar {
+                summary: format!("{} busy block(s) from Calendar.app", blocks.len()),
+                blocks,
+                source: SlotSource::MacosCalendar,
+            }
+        }
+        Err(e) => BusyCalendar::unchecked(format!(
+            "Calendar.app unavailable ({}); all work hours treated as free",
+            e
+        )),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+async fn load_system_calendar(_from: NaiveDateTime, _until: NaiveDateTime) -> BusyCalendar {
+    BusyCalendar::unchecked(
+        "no calendar configured (import an ICS file); all work hours treated as free".into(),
+    )
+}
+
+/// Busy blocks in an ICS document overlapping `from..until`.
+pub(super) fn parse_ics(text: &str, from: NaiveDateTime, until: NaiveDateTime) -> Vec<BusyBlock> {
+    let mut blocks = Vec::new();
+    let mut event: Option<Vec<(String, String, String)>> = None;
+
+    for line in unfold(text) {
+        match line.as_str() {
+            "BEGIN:VEVENT" => event = Some(Vec::new()),
+            "END:VEVENT" => {
+                if let Some(props) = event.take() {
+                    blocks.extend(event_blocks(&props, from, until));
+                }
+            }
+            _ => {
+                if let Some(props) = event.as_mut()
+                    && let Some((head, value)) = line.split_once(':')
+                {
+                    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+                    props.push((name.to_ascii_uppercase(), params.to_string(), value.into()));
+                }
+            }
+        }
+    }
+    blocks.sort_by_key(|b| b.start);
+    blocks
+}
+
+/// Join RFC 5545 folded lines.
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        let raw = raw.trim_end_matches('\r');
+        if let Some(continuation) = raw.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(continuation);
+        } else {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+fn event_blocks(
+    props: &[(String, String, String)],
+    from: NaiveDateTime,
+    until: NaiveDateTime,
+) -> Vec<BusyBlock> {
+    let prop = |name: &str| props.iter().find(|(n, _, _)| n == name);
+    let value = |name: &str| prop(name).map(|(_, _, v)| v.trim());
+
+    if value("STATUS").is_some_and(|s| s.eq_ignore_ascii_case("CANCELLED"))
+        || value("TRANSP").is_some_and(|t| t.eq_ignore_ascii_case("TRANSPARENT"))
+    {
+        return Vec::new();
+    }
+    let Some(Some(start)) = prop("DTSTART").map(|(_, p, v)| parse_time(p, v)) else {
+        // Missing, unparseable or all-day.
+        return Vec::new();
+    };
+    let end = prop("DTEND")
+        .and_then(|(_, p, v)| parse_time(p, v))
+        .or_else(|| {
+            value("DURATION")
+                .and_then(parse_duration)
+                .map(|d| start + d)
+        });
+    let Some(end) = end.filter(|end| *end > start) else {
+        return Vec::new();
+    };
+    let length = end - start;
+    let title = value("SUMMARY").unwrap_or("(busy)").to_string();
+    let excluded: Vec<NaiveDateTime> = props
+        .iter()
+        .filter(|(n, _, _)| n == "EXDATE")
+        .flat_map(|(_, p, v)| v.split(',').filter_map(move |t| parse_time(p, t)))
+        .collect();
+
+    let starts = match value("RRULE") {
+        Some(rule) => expand_rule(start, rule, until),
+        None => vec![start],
+    };
+    starts
+        .into_iter()
+        .filter(|s| !excluded.contains(s))
+        .filter(|s| *s + length > from && *s < until)
+        .map(|s| BusyBlock {
+            start: s,
+            end: s + length,
+            title: title.clone(),
+        })
+        .collect()
+}
+
+/// Parse a DATE-TIME value into local time; `None` for all-day dates.
+fn parse_time(params: &str, value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    let params = params.to_ascii_uppercase();
+    if (params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME")) || value.len() == 8 {
+        return None;
+    }
+    match value.strip_suffix('Z') {
+        Some(utc) => {
+            let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+            Some(
+                Utc.from_utc_datetime(&naive)
+                    .with_timezone(&Local)
+                    .naive_local(),
+            )
+        }
+        None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok(),
+    }
+}
+
+/// Parse a positive ICS duration such as `PT1H30M` or `P1D`.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let rest = value.trim().trim_start_matches('+').strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = std::mem::take(&mut number).parse().ok()?;
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(total)
+}
+
+/// Occurrence starts of a daily or weekly rule, up to `horizon`.
+fn expand_rule(start: NaiveDateTime, rule: &str, horizon: NaiveDateTime) -> Vec<NaiveDateTime> {
+    let parts: Vec<(&str, &str)> = rule.split(';').filter_map(|p| p.split_once('=')).collect();
+    let part = |key: &str| {
+        parts
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| *v)
+    };
+
+    let interval = part("INTERVAL")
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(1);
+    let count = part("COUNT").and_then(|v| v.parse::<usize>().ok());
+    let rule_until = part("UNTIL").and_then(|v| {
+        parse_time("", v).or_else(|| {
+            NaiveDate::parse_from_str(v, "%Y%m%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(23, 59, 59))
+        })
+    });
+    let limit = rule_until.map_or(horizon, |u| u.min(horizon));
+    let max = count.unwrap_or(MAX_OCCURRENCES).min(MAX_OCCURRENCES);
+
+    let mut starts = Vec::new();
+    match part("FREQ").map(str::to_ascii_uppercase).as_deref() {
+        Some("DAILY") => {
+            let mut next = start;
+            while starts.len() < max && next <= limit {
+                starts.push(next);
+                next += Duration::days(interval);
+            }
+        }
+        Some("WEEKLY") => {
+            let mut days: Vec<Weekday> = part("BYDAY")
+                .map(|v| v.split(',').filter_map(parse_weekday).collect())
+                .unwrap_or_default();
+            if days.is_empty() {
+                days.push(start.weekday());
+            }
+            days.sort_by_key(|d| d.num_days_from_monday());
+            let week_start =
+                start - Duration::days(i64::from(start.weekday().num_days_from_monday()));
+            let mut week = week_start;
+            'weeks: while week <= limit {
+                for day in &days {
+                    let next = week + Duration::days(i64::from(day.num_days_from_monday()));
+                    if next < start {
+                        continue;
+                    }
+                    if starts.len() >= max || next > limit {
+                        break 'weeks;
+                    }
+                    starts.push(next);
+                }
+                week += Duration::weeks(interval);
+            }
+        }
+        _ => starts.push(start),
+    }
+    starts
+}
+
+/// Weekday of a `BYDAY` entry, ignoring ordinal prefixes like `1MO`.
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    let code = value
+        .trim()
+        .trim_start_matches(['+', '-', '0', '1', '2', '3', '4', '5']);
+    match code.to_ascii_uppercase().as_str() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    const ICS: &str = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Design review with a very long\r\n  title\r\n\
+DTSTART:20261019T100000\r\n\
+DTEND:20261019T113000\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Standup\r\n\
+DTSTART;TZID=Europe/Berlin:20261019T090000\r\n\
+DURATION:PT15M\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR;COUNT=4\r\n\
+EXDATE;TZID=Europe/Berlin:20261021T090000\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Holiday\r\n\
+DTSTART;VALUE=DATE:20261020\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Focus (free)\r\n\
+DTSTART:20261020T140000\r\n\
+DTEND:20261020T160000\r\n\
+TRANSP:TRANSPARENT\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Cancelled sync\r\n\
+DTSTART:20261020T150000\r\n\
+DTEND:20261020T160000\r\n\
+STATUS:CANCELLED\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    #[test]
+    fn test_parse_ics_busy_blocks() {
+        let blocks = parse_ics(ICS, at("2026-10-19", 0, 0), at("2026-10-26", 0, 0));
+        let summary: Vec<(NaiveDateTime, NaiveDateTime, &str)> = blocks
+            .iter()
+            .map(|b| (b.start, b.end, b.title.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (at("2026-10-19", 9, 0), at("2026-10-19", 9, 15), "Standup"),
+                (
+                    at("2026-10-19", 10, 0),
+                    at("2026-10-19", 11, 30),
+                    "Design review with a very long title"
+                ),
+                (at("2026-10-23", 9, 0), at("2026-10-23", 9, 15), "Standup"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_ics_window_filters_events() {
+        let blocks = parse_ics(ICS, at("2026-10-20", 0, 0), at("2026-10-22", 0, 0));
+        // Wednesday's standup is excluded by EXDATE; Monday's is outside the window.
+        assert!(blocks.is_empty());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("P1D"), Some(Duration::days(1)));
+        assert_eq!(parse_duration("P1W"), Some(Duration::weeks(1)));
+        assert_eq!(parse_duration("1H"), None);
+    }
+
+    #[test]
+    fn test_expand_daily_rule_until() {
+        let starts = expand_rule(
+            at("2026-10-19", 8, 0),
+            "FREQ=DAILY;INTERVAL=2;UNTIL=20261024T000000",
+            at("2026-12-31", 0, 0),
+        );
+        assert_eq!(
+            starts,
+            vec![
+                at("2026-10-19", 8, 0),
+                at("2026-10-21", 8, 0),
+                at("2026-10-23", 8, 0)
+            ]
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Energy calibration — learns productive hours from what actually happened.
+//!
+//! The static [`EnergyProfile`] is a guess. Completion timestamps and
+//! self-reported check-ins are folded into a per-hour score: the share of
+//! completions landing in each hour (relative to the busiest hour), averaged
+//! with the mean check-in level for that hour. Hours without data fall back
+//! to the profile. The model is recomputed weekly.
+
+use super::EnergyProfile;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Completions and check-ins older than this are ignored.
+const WINDOW_DAYS: i64 = 56;
+
+/// Samples needed before the calibrated model replaces the profile.
+pub(super) const MIN_SAMPLES: usize = 10;
+
+/// A calibration older than this is recomputed.
+const RECALIBRATE_AFTER_DAYS: i64 = 7;
+
+/// Completion and check-in events kept in state.
+pub(super) const MAX_EVENTS: usize = 1000;
+
+/// A deadline marked complete, with the local hour it happened in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct CompletionEvent {
+    pub deadline_id: usize,
+    pub at: DateTime<Utc>,
+    pub hour: u8,
+}
+
+/// A self-reported energy level for an hour of the day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct EnergyCheckin {
+    pub at: DateTime<Utc>,
+    pub hour: u8,
+    /// 1 (drained) to 5 (sharp).
+    pub level: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct EnergyCalibration {
+    pub computed_at: DateTime<Utc>,
+    /// Score per hour of day in 0.0–1.0; `None` where nothing was observed.
+    pub hourly: Vec<Option<f64>>,
+    pub completions: usize,
+    pub checkins: usize,
+}
+
+impl EnergyCalibration {
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        now - self.computed_at >= Duration::days(RECALIBRATE_AFTER_DAYS)
+    }
+
+    /// The `n` best-scoring hours, best first.
+    pub fn peak_hours(&self, n: usize) -> Vec<u8> {
+        let mut hours: Vec<(u8, f64)> = self
+            .hourly
+            .iter()
+            .enumerate()
+            .filter_map(|(h, score)| score.map(|s| (h as u8, s)))
+            .filter(|(_, s)| *s > 0.0)
+            .collect();
+        hours.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        hours.into_iter().take(n).map(|(h, _)| h).collect()
+    }
+}
+
+/// Fit the per-hour model, or `None` when there are fewer than
+/// [`MIN_SAMPLES`] recent events.
+pub(super) fn calibrate(
+    completions: &[CompletionEvent],
+    checkins: &[EnergyCheckin],
+    now: DateTime<Utc>,
+) -> Option<EnergyCalibration> {
+    let since = now - Duration::days(WINDOW_DAYS);
+
+    let mut counts = [0usize; 24];
+    let mut completion_total = 0;
+    for event in completions.iter().filter(|e| e.at >= since && e.hour < 24) {
+        counts[event.hour as usize] += 1;
+        completion_total += 1;
+    }
+
+    let mut level_sum = [0.0f64; 24];
+    let mut level_count = [0usize; 24];
+    let mut checkin_total = 0;
+    for checkin in checkins.iter().filter(|c| c.at >= since && c.hour < 24) {
+        level_sum[checkin.hour as usize] += f64::from(checkin.level.clamp(1, 5) - 1) / 4.0;
+        level_count[checkin.hour as usize] += 1;
+        checkin_total += 1;
+    }
+
+    if completion_total + checkin_total < MIN_SAMPLES {
+        return None;
+    }
+
+    let busiest = counts.iter().copied().max().unwrap_or(0);
+    let hourly = (0..24)
+        .map(|h| {
+            let rate = (busiest > 0).then(|| counts[h] as f64 / busiest as f64);
+            let reported = (level_count[h] > 0).then(|| level_sum[h] / level_count[h] as f64);
+            match (rate, reported) {
+                (Some(rate), Some(reported)) => Some((rate + reported) / 2.0),
+                (rate, reported) => rate.or(reported),
+            }
+        })
+        .collect();
+
+    Some(EnergyCalibration {
+        computed_at: now,
+        hourly,
+        completions: completion_total,
+        checkins: checkin_total,
+    })
+}
+
+/// Score of the static profile for `hour`.
+pub(super) fn profile_score(profile: &EnergyProfile, hour: u8) -> f64 {
+    if profile.peak_hours.contains(&hour) {
+        1.0
+    } else if profile.low_energy_hours.contains(&hour) {
+        0.2
+    } else {
+        0.6
+    }
+}
+
+/// Energy score for `hour` and where it came from.
+pub(super) fn energy_score(
+    profile: &EnergyProfile,
+    calibration: Option<&EnergyCalibration>,
+    hour: u8,
+) -> (f64, &'static str) {
+    match calibration.and_then(|c| c.hourly.get(hour as usize).copied().flatten()) {
+        Some(score) => (score, "calibrated"),
+        None => (profile_score(profile, hour), "profile"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completion(hour: u8, days_ago: i64, now: DateTime<Utc>) -> CompletionEvent {
+        CompletionEvent {
+            deadline_id: 1,
+            at: now - Duration::days(days_ago),
+            hour,
+        }
+    }
+
+    #[test]
+    fn test_calibration_learns_completion_hours() {
+        let now = Utc::now();
+        let mut completions: Vec<CompletionEvent> =
+            (0..6).map(|d| completion(16, d, now)).collect();
+        completions.extend((0..3).map(|d| completion(10, d, now)));
+        // Outside the window; ignored.
+        completions.extend((0..20).map(|_| completion(9, WINDOW_DAYS + 1, now)));
+        let checkins = vec![EnergyCheckin {
+            at: now,
+            hour: 16,
+            level: 5,
+        }];
+
+        let calibration = calibrate(&completions, &checkins, now).unwrap();
+        assert_eq!(calibration.completions, 9);
+        assert_eq!(calibration.checkins, 1);
+        assert_eq!(calibration.hourly[16], Some(1.0));
+        assert_eq!(calibration.hourly[10], Some(0.5));
+        assert_eq!(calibration.hourly[9], Some(0.0));
+        assert_eq!(calibration.peak_hours(2), vec![16, 10]);
+
+        let profile = EnergyProfile::default();
+        assert_eq!(
+            energy_score(&profile, Some(&calibration), 16),
+            (1.0, "calibrated")
+        );
+        assert_eq!(energy_score(&profile, None, 9), (1.0, "profile"));
+    }
+
+    #[test]
+    fn test_calibration_needs_enough_samples() {
+        let now = Utc::now();
+        let completions: Vec<CompletionEvent> = (0..3).map(|d| completion(9, d, now)).collect();
+        assert!(calibrate(&completions, &[], now).is_none());
+    }
+
+    #[test]
+    fn test_calibration_goes_stale_weekly() {
+        let now = Utc::now();
+        let calibration = EnergyCalibration {
+            computed_at: now - Duration::days(8),
+            hourly: vec![None; 24],
+            completions: 10,
+            checkins: 0,
+        };
+        assert!(calibration.is_stale(now));
+        assert!(!calibration.is_stale(now - Duration::days(2)));
+    }
+}
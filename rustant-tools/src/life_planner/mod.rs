@@ -1,16 +1,31 @@
 //! Life planner tool — energy-aware scheduling, deadlines, habits, context switching.
+//!
+//! `schedule` places open deadlines into free one-hour slots of the work
+//! day. Slot energy comes from the static profile until enough completions
+//! and check-ins exist to calibrate it; busy time comes from an imported ICS
+//! file or Calendar.app, and without either every work hour counts as free.
+
+mod calendar;
+mod energy;
+mod schedule;
 
 use async_trait::async_trait;
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Local, NaiveDate, Timelike, Utc};
 use rustant_core::error::ToolError;
 use rustant_core::types::{RiskLevel, ToolOutput};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crate::registry::Tool;
+use calendar::SlotSource;
+use energy::{CompletionEvent, EnergyCalibration, EnergyCheckin, MAX_EVENTS, MIN_SAMPLES};
+use schedule::Placement;
+
+/// Longest window `schedule` and `replan` plan ahead.
+const MAX_SCHEDULE_DAYS: u32 = 28;
 
 // ---------------------------------------------------------------------------
 // Data models
@@ -82,6 +97,8 @@ struct Deadline {
     status: DeadlineStatus,
     notes: String,
     created_at: DateTime<Utc>,
+    #[serde(default)]
+    completed_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -110,6 +127,15 @@ struct HabitEntry {
     target_streak: u32,
     history: Vec<String>,
     created_at: DateTime<Utc>,
+    /// Hour of day the habit is protected in generated schedules.
+    #[serde(default)]
+    preferred_hour: Option<u8>,
+    #[serde(default = "default_habit_minutes")]
+    duration_mins: u32,
+}
+
+fn default_habit_minutes() -> u32 {
+    30
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -120,6 +146,29 @@ struct ContextSwitchEntry {
     reason: String,
 }
 
+/// Work day and calendar settings for generated schedules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct SchedulePrefs {
+    work_start_hour: u8,
+    work_end_hour: u8,
+    /// Deadlines at or above this priority may take a pinned habit's slot.
+    habit_override_priority: DeadlinePriority,
+    /// Imported ICS file providing busy blocks.
+    ics_path: Option<PathBuf>,
+}
+
+impl Default for SchedulePrefs {
+    fn default() -> Self {
+        Self {
+            work_start_hour: 9,
+            work_end_hour: 18,
+            habit_override_priority: DeadlinePriority::High,
+            ics_path: None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct PlannerState {
     energy_profile: EnergyProfile,
@@ -128,6 +177,17 @@ struct PlannerState {
     context_switches: Vec<ContextSwitchEntry>,
     next_deadline_id: usize,
     next_habit_id: usize,
+    #[serde(default)]
+    completions: Vec<CompletionEvent>,
+    #[serde(default)]
+    energy_checkins: Vec<EnergyCheckin>,
+    #[serde(default)]
+    calibration: Option<EnergyCalibration>,
+    #[serde(default)]
+    schedule_prefs: SchedulePrefs,
+    /// Most recently generated schedule, for `replan` to diff against.
+    #[serde(default)]
+    schedule: Vec<Placement>,
 }
 
 impl Default for PlannerState {
@@ -139,6 +199,11 @@ impl Default for PlannerState {
             context_switches: Vec::new(),
             next_deadline_id: 1,
             next_habit_id: 1,
+            completions: Vec::new(),
+            energy_checkins: Vec::new(),
+            calibration: None,
+            schedule_prefs: SchedulePrefs::default(),
+            schedule: Vec::new(),
         }
     }
 }
@@ -221,6 +286,68 @@ impl LifePlannerTool {
         }
     }
 
+    /// Optional hour-of-day argument, validated to 0-23.
+    fn parse_hour(args: &Value, field: &str) -> Result<Option<u8>, ToolError> {
+        match args.get(field).and_then(|v| v.as_u64()) {
+            Some(h) if h <= 23 => Ok(Some(h as u8)),
+            Some(h) => Err(ToolError::InvalidArguments {
+                name: "life_planner".to_string(),
+                reason: format!("Invalid {} {}: must be 0-23", field, h),
+            }),
+            None => Ok(None),
+        }
+    }
+
+    fn require_deadline_id(args: &Value, state: &PlannerState) -> Result<usize, ToolError> {
+        let id = args
+            .get("deadline_id")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .ok_or_else(|| ToolError::InvalidArguments {
+                name: "life_planner".to_string(),
+                reason: "deadline_id is required".to_string(),
+            })?;
+        state
+            .deadlines
+            .iter()
+            .position(|d| d.id == id)
+            .ok_or_else(|| ToolError::InvalidArguments {
+                name: "life_planner".to_string(),
+                reason: format!("Deadline #{} not found", id),
+            })
+    }
+
+    /// Recompute the energy model when it is missing or a week old.
+    /// Returns whether the state changed.
+    fn refresh_calibration(state: &mut PlannerState) -> bool {
+        let now = Utc::now();
+        if state.calibration.as_ref().is_some_and(|c| !c.is_stale(now)) {
+            return false;
+        }
+        let fresh = energy::calibrate(&state.completions, &state.energy_checkins, now);
+        let changed = fresh.is_some() || state.calibration.is_some();
+        state.calibration = fresh;
+        changed
+    }
+
+    /// One line on which energy model schedules are scored with.
+    fn energy_model_summary(state: &PlannerState) -> String {
+        match &state.calibration {
+            Some(c) => format!(
+                "calibrated {} from {} completion(s) and {} check-in(s); best hours {:?}",
+                c.computed_at.format("%Y-%m-%d"),
+                c.completions,
+                c.checkins,
+                c.peak_hours(3)
+            ),
+            None => format!(
+                "static profile ({} of {} samples needed to calibrate)",
+                state.completions.len() + state.energy_checkins.len(),
+                MIN_SAMPLES
+            ),
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Actions
     // -----------------------------------------------------------------------
@@ -374,6 +501,7 @@ impl LifePlannerTool {
             status: status.clone(),
             notes,
             created_at: Utc::now(),
+            completed_at: None,
         });
         self.save_state(state)?;
 
@@ -481,6 +609,11 @@ impl LifePlannerTool {
                 .get("target_streak")
                 .and_then(|v| v.as_u64())
                 .unwrap_or(30) as u32;
+            let preferred_hour = Self::parse_hour(args, "preferred_hour")?;
+            let duration_mins = args
+                .get("duration_mins")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(30) as u32;
 
             let id = state.next_habit_id;
             state.next_habit_id += 1;
@@ -495,6 +628,8 @@ impl LifePlannerTool {
                 target_streak,
                 history: vec![today],
                 created_at: Utc::now(),
+                preferred_hour,
+                duration_mins,
             });
             self.save_state(state)?;
 
@@ -522,6 +657,12 @@ impl LifePlannerTool {
             "Energy Profile:\n  Peak hours: {:?}\n  Low energy hours: {:?}\n  Break every {} mins\n\n",
             profile.peak_hours, profile.low_energy_hours, profile.preferred_break_interval_mins
         ));
+        if let Some(calibration) = &state.calibration {
+            output.push_str(&format!(
+                "Calibrated best hours: {:?}\n\n",
+                calibration.peak_hours(3)
+            ));
+        }
 
         // Today's deadlines sorted by priority
         let mut todays_deadlines: Vec<&Deadline> = state
@@ -990,6 +1131,377 @@ impl LifePlannerTool {
 
         Ok(ToolOutput::text(output))
     }
+
+    fn action_complete_deadline(
+        &self,
+        args: &Value,
+        state: &mut PlannerState,
+    ) -> Result<ToolOutput, ToolError> {
+        let index = Self::require_deadline_id(args, state)?;
+        let deadline = &mut state.deadlines[index];
+        if deadline.status == DeadlineStatus::Completed {
+            return Ok(ToolOutput::text(format!(
+                "Deadline #{} '{}' is already completed.",
+                deadline.id, deadline.title
+            )));
+        }
+        let now = Utc::now();
+        deadline.status = DeadlineStatus::Completed;
+        deadline.completed_at = Some(now);
+        let (id, title) = (deadline.id, deadline.title.clone());
+
+        state.completions.push(CompletionEvent {
+            deadline_id: id,
+            at: now,
+            hour: Local::now().hour() as u8,
+        });
+        if state.completions.len() > MAX_EVENTS {
+            state
+                .completions
+                .drain(0..state.completions.len() - MAX_EVENTS);
+        }
+        state.schedule.retain(|p| p.deadline_id != Some(id));
+        self.save_state(state)?;
+
+        Ok(ToolOutput::text(format!(
+            "Deadline #{} '{}' completed.",
+            id, title
+        )))
+    }
+
+    fn action_energy_checkin(
+        &self,
+        args: &Value,
+        state: &mut PlannerState,
+    ) -> Result<ToolOutput, ToolError> {
+        let level = args
+            .get("level")
+            .and_then(|v| v.as_u64())
+            .filter(|l| (1..=5).contains(l))
+            .ok_or_else(|| ToolError::InvalidArguments {
+                name: "life_planner".to_string(),
+                reason: "level is required and must be 1-5".to_string(),
+            })? as u8;
+        let hour = match Self::parse_hour(args, "hour")? {
+            Some(hour) => hour,
+            None => Local::now().hour() as u8,
+        };
+
+        state.energy_checkins.push(EnergyCheckin {
+            at: Utc::now(),
+            hour,
+            level,
+        });
+        if state.energy_checkins.len() > MAX_EVENTS {
+            state
+                .energy_checkins
+                .drain(0..state.energy_checkins.len() - MAX_EVENTS);
+        }
+        self.save_state(state)?;
+
+        Ok(ToolOutput::text(format!(
+            "Energy check-in recorded: {}/5 at {:02}:00 ({} check-ins total).",
+            level,
+            hour,
+            state.energy_checkins.len()
+        )))
+    }
+
+    fn action_calibrate(&self, state: &mut PlannerState) -> Result<ToolOutput, ToolError> {
+        state.calibration =
+            energy::calibrate(&state.completions, &state.energy_checkins, Utc::now());
+        self.save_state(state)?;
+
+        let Some(calibration) = &state.calibration else {
+            return Ok(ToolOutput::text(format!(
+                "Not enough data to calibrate: {} of {} samples. Complete deadlines with \
+                 complete_deadline or record energy_checkin entries; scheduling keeps using \
+                 the static energy profile.",
+                state.completions.len() + state.energy_checkins.len(),
+                MIN_SAMPLES
+            )));
+        };
+
+        let mut output = format!(
+            "=== Energy Calibration ===\n\nFrom {} completion(s) and {} check-in(s).\n\n",
+            calibration.completions, calibration.checkins
+        );
+        for hour in 0..24u8 {
+            if let Some(score) = calibration.hourly[hour as usize] {
+                output.push_str(&format!(
+                    "  {:02}:00  {:<10} {:.2}\n",
+                    hour,
+                    "#".repeat((score * 10.0).round() as usize),
+                    score
+                ));
+            }
+        }
+        output.push_str(&format!(
+            "\nBest hours: {:?} (profile peak hours: {:?})\n",
+            calibration.peak_hours(3),
+            state.energy_profile.peak_hours
+        ));
+        Ok(ToolOutput::text(output))
+    }
+
+    fn action_import_calendar(
+        &self,
+        args: &Value,
+        state: &mut PlannerState,
+    ) -> Result<ToolOutput, ToolError> {
+        let raw = args.get("ics_path").and_then(|v| v.as_str()).unwrap_or("");
+        if raw.is_empty() {
+            return Err(ToolError::InvalidArguments {
+                name: "life_planner".to_string(),
+                reason: "ics_path is required".to_string(),
+            });
+        }
+        let path = self.workspace.join(raw);
+        let text = std::fs::read_to_string(&path).map_err(|e| ToolError::InvalidArguments {
+            name: "life_planner".to_string(),
+            reason: format!("Cannot read ICS file '{}': {}", raw, e),
+        })?;
+        if !text.contains("BEGIN:VCALENDAR") {
+            return Err(ToolError::InvalidArguments {
+                name: "life_planner".to_string(),
+                reason: format!("'{}' is not an ICS calendar", raw),
+            });
+        }
+        let now = Local::now().naive_local();
+        let upcoming = calendar::parse_ics(&text, now, now + chrono::Duration::days(7));
+
+        state.schedule_prefs.ics_path = Some(path);
+        self.save_state(state)?;
+
+        Ok(ToolOutput::text(format!(
+            "Calendar imported from '{}': {} busy block(s) in the next 7 days. \
+             The file is re-read each time a schedule is built.",
+            raw,
+            upcoming.len()
+        )))
+    }
+
+    fn action_configure_schedule(
+        &self,
+        args: &Value,
+        state: &mut PlannerState,
+    ) -> Result<ToolOutput, ToolError> {
+        let mut prefs = state.schedule_prefs.clone();
+        if let Some(start) = Self::parse_hour(args, "work_start_hour")? {
+            prefs.work_start_hour = start;
+        }
+        match args.get("work_end_hour").and_then(|v| v.as_u64()) {
+            Some(end) if end <= 24 => prefs.work_end_hour = end as u8,
+            Some(end) => {
+                return Err(ToolError::InvalidArguments {
+                    name: "life_planner".to_string(),
+                    reason: format!("Invalid work_end_hour {}: must be 1-24", end),
+                });
+            }
+            None => {}
+        }
+        if prefs.work_start_hour >= prefs.work_end_hour {
+            return Err(ToolError::InvalidArguments {
+                name: "life_planner".to_string(),
+                reason: "work_start_hour must be before work_end_hour".to_string(),
+            });
+        }
+        if let Some(priority) = args.get("habit_override_priority").and_then(|v| v.as_str()) {
+            prefs.habit_override_priority = Self::parse_priority(priority);
+        }
+        if args.get("clear_calendar").and_then(|v| v.as_bool()) == Some(true) {
+            prefs.ics_path = None;
+        }
+
+        let mut pinned = String::new();
+        if let Some(habit_id) = args.get("habit_id").and_then(|v| v.as_u64()) {
+            let habit = state
+                .habits
+                .iter_mut()
+                .find(|h| h.id == habit_id as usize)
+                .ok_or_else(|| ToolError::InvalidArguments {
+                    name: "life_planner".to_string(),
+                    reason: format!("Habit #{} not found", habit_id),
+                })?;
+            habit.preferred_hour = Self::parse_hour(args, "preferred_hour")?;
+            if let Some(mins) = args.get("duration_mins").and_then(|v| v.as_u64()) {
+                habit.duration_mins = mins as u32;
+            }
+            pinned = match habit.preferred_hour {
+                Some(hour) => format!(
+                    "\n  Habit '{}' pinned at {:02}:00 for {} mins",
+                    habit.name, hour, habit.duration_mins
+                ),
+                None => format!("\n  Habit '{}' unpinned", habit.name),
+            };
+        }
+
+        state.schedule_prefs = prefs;
+        self.save_state(state)?;
+
+        let prefs = &state.schedule_prefs;
+        Ok(ToolOutput::text(format!(
+            "Schedule preferences updated.\n  Work hours: {:02}:00–{:02}:00\n  Habits yield to: {} priority and above\n  Calendar: {}{}",
+            prefs.work_start_hour,
+            prefs.work_end_hour,
+            prefs.habit_override_priority,
+            prefs
+                .ics_path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| "system default".to_string()),
+            pinned
+        )))
+    }
+
+    /// Build a schedule from now and store it.
+    async fn build_schedule(
+        &self,
+        args: &Value,
+        state: &mut PlannerState,
+    ) -> Result<(schedule::Plan, String), ToolError> {
+        let days = args
+            .get("days")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(7)
+            .clamp(1, u64::from(MAX_SCHEDULE_DAYS)) as u32;
+        let now = Local::now().naive_local();
+        let until = (now.date() + chrono::Duration::days(i64::from(days)))
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or(now);
+        let busy = calendar::load_busy(state.schedule_prefs.ics_path.as_deref(), now, until).await;
+        let plan = schedule::plan(state, now, days, &busy.blocks, busy.source);
+
+        let header = format!(
+            "Energy: {}\nCalendar: {}\nWork hours: {:02}:00–{:02}:00{}\n",
+            Self::energy_model_summary(state),
+            busy.summary,
+            state.schedule_prefs.work_start_hour,
+            state.schedule_prefs.work_end_hour,
+            if busy.source == SlotSource::Unchecked {
+                " (without a calendar, meetings are not avoided)"
+            } else {
+                ""
+            }
+        );
+        state.schedule = plan.placements.clone();
+        self.save_state(state)?;
+        Ok((plan, header))
+    }
+
+    async fn action_schedule(
+        &self,
+        args: &Value,
+        state: &mut PlannerState,
+    ) -> Result<ToolOutput, ToolError> {
+        let (plan, header) = self.build_schedule(args, state).await?;
+        Ok(ToolOutput::text(format!(
+            "=== Schedule ===\n{}{}",
+            header,
+            schedule::render(&plan, state)
+        )))
+    }
+
+    async fn action_replan(
+        &self,
+        args: &Value,
+        state: &mut PlannerState,
+    ) -> Result<ToolOutput, ToolError> {
+        let index = Self::require_deadline_id(args, state)?;
+        let mut slipped = Vec::new();
+        if let Some(due) = args.get("due_date").and_then(|v| v.as_str()) {
+            let parsed = NaiveDate::parse_from_str(due, "%Y-%m-%d").map_err(|_| {
+                ToolError::InvalidArguments {
+                    name: "life_planner".to_string(),
+                    reason: format!("Invalid due_date '{}': expected YYYY-MM-DD", due),
+                }
+            })?;
+            let deadline = &mut state.deadlines[index];
+            slipped.push(format!("due {} → {}", deadline.due_date, due));
+            deadline.due_date = due.to_string();
+            if deadline.status != DeadlineStatus::Completed {
+                deadline.status = if parsed < Utc::now().date_naive() {
+                    DeadlineStatus::Overdue
+                } else {
+                    DeadlineStatus::Pending
+                };
+            }
+        }
+        if let Some(hours) = args.get("remaining_hours").and_then(|v| v.as_f64()) {
+            let deadline = &mut state.deadlines[index];
+            slipped.push(format!(
+                "{:.1}h → {:.1}h remaining",
+                deadline.estimated_hours, hours
+            ));
+            deadline.estimated_hours = hours.max(0.0);
+        }
+
+        let previous = std::mem::take(&mut state.schedule);
+        let (plan, header) = self.build_schedule(args, state).await?;
+        let changes = schedule::changes(&previous, &plan, Local::now().naive_local());
+
+        let deadline = &state.deadlines[index];
+        let mut output = format!(
+            "=== Replan: #{} '{}' ===\n{}\n",
+            deadline.id,
+            deadline.title,
+            if slipped.is_empty() {
+                "Reflowed from now.".to_string()
+            } else {
+                format!("Slipped: {}.", slipped.join(", "))
+            }
+        );
+        if changes.is_empty() {
+            output.push_str("Nothing else was displaced.\n");
+        } else {
+            output.push_str("Displaced:\n");
+            for line in &changes {
+                output.push_str(&format!("  - {}\n", line));
+            }
+        }
+        output.push('\n');
+        output.push_str(&header);
+        output.push_str(&schedule::render(&plan, state));
+        Ok(ToolOutput::text(output))
+    }
+}
+
+/// Briefing summary of open deadlines that are overdue or due between `today`
+/// and `last_day` (inclusive).
+pub fn deadlines_briefing(workspace: &Path, today: NaiveDate, last_day: NaiveDate) -> String {
+    let state = LifePlannerTool::new(workspace.to_path_buf()).load_state();
+    let mut open: Vec<(NaiveDate, &Deadline)> = state
+        .deadlines
+        .iter()
+        .filter(|d| d.status != DeadlineStatus::Completed)
+        .filter_map(|d| {
+            NaiveDate::parse_from_str(&d.due_date, "%Y-%m-%d")
+                .ok()
+                .map(|due| (due, d))
+        })
+        .filter(|(due, _)| *due <= last_day)
+        .collect();
+    if open.is_empty() {
+        return "No deadlines coming up.".into();
+    }
+    open.sort_by_key(|(due, _)| *due);
+    let mut out = String::new();
+    for (due, deadline) in open {
+        if due < today {
+            out.push_str(&format!(
+                "- **Overdue** {} (due {}, {})\n",
+                deadline.title, due, deadline.priority
+            ));
+        } else {
+            out.push_str(&format!(
+                "- {}: {} ({})\n",
+                due.format("%a %b %-d"),
+                deadline.title,
+                deadline.priority
+            ));
+        }
+    }
+    out
 }
 
 /// Returns a date string N days from today in YYYY-MM-DD format.
@@ -1006,7 +1518,7 @@ impl Tool for LifePlannerTool {
     }
 
     fn description(&self) -> &str {
-        "Personal productivity: energy-aware scheduling, deadlines, habits, context switching. Actions: set_energy_profile, add_deadline, log_habit, daily_plan, weekly_review, context_switch_log, balance_report, optimize_schedule."
+        "Personal productivity: energy-aware scheduling, deadlines, habits, context switching. Actions: set_energy_profile, add_deadline, complete_deadline, log_habit, energy_checkin, calibrate, import_calendar, configure_schedule, schedule, replan, daily_plan, weekly_review, context_switch_log, balance_report, optimize_schedule. schedule places open deadlines into free calendar slots by energy; replan reflows after a deadline slips and reports what moved."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -1018,7 +1530,14 @@ impl Tool for LifePlannerTool {
                     "enum": [
                         "set_energy_profile",
                         "add_deadline",
+                        "complete_deadline",
                         "log_habit",
+                        "energy_checkin",
+                        "calibrate",
+                        "import_calendar",
+                        "configure_schedule",
+                        "schedule",
+                        "replan",
                         "daily_plan",
                         "weekly_review",
                         "context_switch_log",
@@ -1068,7 +1587,7 @@ impl Tool for LifePlannerTool {
                 },
                 "habit_id": {
                     "type": "integer",
-                    "description": "Habit ID to mark as completed (for log_habit)"
+                    "description": "Habit ID to mark as completed (for log_habit) or to pin (for configure_schedule)"
                 },
                 "name": {
                     "type": "string",
@@ -1094,6 +1613,55 @@ impl Tool for LifePlannerTool {
                 "reason": {
                     "type": "string",
                     "description": "Reason for context switch"
+                },
+                "deadline_id": {
+                    "type": "integer",
+                    "description": "Deadline ID (for complete_deadline, replan)"
+                },
+                "remaining_hours": {
+                    "type": "number",
+                    "description": "Hours of work left on the slipped deadline (for replan)"
+                },
+                "level": {
+                    "type": "integer",
+                    "description": "Self-reported energy 1 (drained) to 5 (sharp) (for energy_checkin)"
+                },
+                "hour": {
+                    "type": "integer",
+                    "description": "Hour 0-23 the check-in refers to (default: now)"
+                },
+                "ics_path": {
+                    "type": "string",
+                    "description": "ICS calendar file providing busy blocks (for import_calendar)"
+                },
+                "days": {
+                    "type": "integer",
+                    "description": "Days to schedule ahead, 1-28 (for schedule, replan; default: 7)"
+                },
+                "work_start_hour": {
+                    "type": "integer",
+                    "description": "First work hour 0-23 (for configure_schedule; default: 9)"
+                },
+                "work_end_hour": {
+                    "type": "integer",
+                    "description": "Hour the work day ends 1-24 (for configure_schedule; default: 18)"
+                },
+                "habit_override_priority": {
+                    "type": "string",
+                    "enum": ["low", "medium", "high", "critical"],
+                    "description": "Lowest deadline priority allowed to take a pinned habit's slot (default: high)"
+                },
+                "clear_calendar": {
+                    "type": "boolean",
+                    "description": "Forget the imported ICS file (for configure_schedule)"
+                },
+                "preferred_hour": {
+                    "type": "integer",
+                    "description": "Hour 0-23 to protect for a habit in schedules (for log_habit, configure_schedule with habit_id)"
+                },
+                "duration_mins": {
+                    "type": "integer",
+                    "description": "Minutes a pinned habit takes (default: 30)"
                 }
             },
             "required": ["action"]
@@ -1111,18 +1679,30 @@ impl Tool for LifePlannerTool {
     async fn execute(&self, args: Value) -> Result<ToolOutput, ToolError> {
         let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("");
         let mut state = self.load_state();
+        if matches!(action, "daily_plan" | "schedule" | "replan")
+            && Self::refresh_calibration(&mut state)
+        {
+            self.save_state(&state)?;
+        }
 
         match action {
             "set_energy_profile" => self.action_set_energy_profile(&args, &mut state),
             "add_deadline" => self.action_add_deadline(&args, &mut state),
+            "complete_deadline" => self.action_complete_deadline(&args, &mut state),
             "log_habit" => self.action_log_habit(&args, &mut state),
+            "energy_checkin" => self.action_energy_checkin(&args, &mut state),
+            "calibrate" => self.action_calibrate(&mut state),
+            "import_calendar" => self.action_import_calendar(&args, &mut state),
+            "configure_schedule" => self.action_configure_schedule(&args, &mut state),
+            "schedule" => self.action_schedule(&args, &mut state).await,
+            "replan" => self.action_replan(&args, &mut state).await,
             "daily_plan" => self.action_daily_plan(&state),
             "weekly_review" => self.action_weekly_review(&state),
             "context_switch_log" => self.action_context_switch_log(&args, &mut state),
             "balance_report" => self.action_balance_report(&state),
             "optimize_schedule" => self.action_optimize_schedule(&state),
             _ => Ok(ToolOutput::text(format!(
-                "Unknown action: '{}'. Use: set_energy_profile, add_deadline, log_habit, daily_plan, weekly_review, context_switch_log, balance_report, optimize_schedule",
+                "Unknown action: '{}'. Use: set_energy_profile, add_deadline, complete_deadline, log_habit, energy_checkin, calibrate, import_calendar, configure_schedule, schedule, replan, daily_plan, weekly_review, context_switch_log, balance_report, optimize_schedule",
                 action
             ))),
         }
@@ -1145,6 +1725,30 @@ mod tests {
         (dir, tool)
     }
 
+    #[tokio::test]
+    async fn test_deadlines_briefing_covers_overdue_and_window() {
+        let (dir, tool) = make_tool();
+        let workspace = dir.path().canonicalize().unwrap();
+        for (title, due) in [
+            ("Tax return", "2020-04-15"),
+            ("Ship release", &upcoming_date_str(2)),
+            ("Conference talk", &upcoming_date_str(30)),
+        ] {
+            tool.execute(json!({"action": "add_deadline", "title": title, "due_date": due}))
+                .await
+                .unwrap();
+        }
+        let today = Utc::now().date_naive();
+        let briefing = deadlines_briefing(&workspace, today, today + chrono::Duration::days(6));
+        assert!(briefing.starts_with("- **Overdue** Tax return (due 2020-04-15"));
+        assert!(briefing.contains("Ship release"));
+        assert!(!briefing.contains("Conference talk"));
+        assert_eq!(
+            deadlines_briefing(&workspace, today, today),
+            "- **Overdue** Tax return (due 2020-04-15, Medium)\n"
+        );
+    }
+
     #[test]
     fn test_tool_properties() {
         let (_dir, tool) = make_tool();
@@ -1175,7 +1779,9 @@ mod tests {
         assert!(actions.contains(&"context_switch_log"));
         assert!(actions.contains(&"balance_report"));
         assert!(actions.contains(&"optimize_schedule"));
-        assert_eq!(actions.len(), 8);
+        assert!(actions.contains(&"schedule"));
+        assert!(actions.contains(&"replan"));
+        assert_eq!(actions.len(), 15);
     }
 
     #[tokio::test]
@@ -1509,6 +2115,129 @@ mod tests {
         assert!(tool.state_path().exists());
     }
 
+    #[tokio::test]
+    async fn test_completions_and_checkins_calibrate() {
+        let (_dir, tool) = make_tool();
+        tool.execute(
+            json!({"action": "add_deadline", "title": "Report", "due_date": "2099-01-01"}),
+        )
+        .await
+        .unwrap();
+        let result = tool
+            .execute(json!({"action": "complete_deadline", "deadline_id": 1}))
+            .await
+            .unwrap();
+        assert!(result.content.contains("completed"));
+
+        let result = tool.execute(json!({"action": "calibrate"})).await.unwrap();
+        assert!(result.content.contains("1 of 10 samples"));
+
+        for hour in 8..17 {
+            tool.execute(json!({"action": "energy_checkin", "level": 4, "hour": hour}))
+                .await
+                .unwrap();
+        }
+        let result = tool.execute(json!({"action": "calibrate"})).await.unwrap();
+        assert!(result.content.contains("Energy Calibration"));
+        assert!(result.content.contains("1 completion(s) and 9 check-in(s)"));
+
+        let state = tool.load_state();
+        assert_eq!(state.deadlines[0].status, DeadlineStatus::Completed);
+        assert!(state.deadlines[0].completed_at.is_some());
+        assert_eq!(state.completions.len(), 1);
+        assert!(state.calibration.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_schedule_uses_imported_calendar() {
+        let (dir, tool) = make_tool();
+        let tomorrow = Utc::now().date_naive() + chrono::Duration::days(1);
+        let stamp = tomorrow.format("%Y%m%d");
+        std::fs::write(
+            dir.path().join("work.ics"),
+            format!(
+                "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:Offsite\nDTSTART:{stamp}T090000\nDTEND:{stamp}T120000\nEND:VEVENT\nEND:VCALENDAR\n"
+            ),
+        )
+        .unwrap();
+        let result = tool
+            .execute(json!({"action": "import_calendar", "ics_path": "work.ics"}))
+            .await
+            .unwrap();
+        assert!(result.content.contains("Calendar imported"));
+
+        tool.execute(
+            json!({"action": "configure_schedule", "work_start_hour": 9, "work_end_hour": 12}),
+        )
+        .await
+        .unwrap();
+        tool.execute(json!({
+            "action": "add_deadline",
+            "title": "Quarterly plan",
+            "due_date": upcoming_date_str(3),
+            "estimated_hours": 2.0
+        }))
+        .await
+        .unwrap();
+
+        let result = tool
+            .execute(json!({"action": "schedule", "days": 4}))
+            .await
+            .unwrap();
+        assert!(result.content.contains("busy block(s) from ICS file"));
+        assert!(result.content.contains("09:00–12:00  busy: Offsite"));
+        assert!(result.content.contains("free in ICS calendar"));
+        assert!(result.content.contains("(profile)"));
+
+        let state = tool.load_state();
+        assert!(!state.schedule.is_empty());
+        assert!(state.schedule.iter().all(|p| p.start.date() != tomorrow));
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[tokio::test]
+    async fn test_schedule_without_calendar_assumes_free() {
+        let (_dir, tool) = make_tool();
+        tool.execute(
+            json!({"action": "add_deadline", "title": "Essay", "due_date": upcoming_date_str(2)}),
+        )
+        .await
+        .unwrap();
+        let result = tool.execute(json!({"action": "schedule"})).await.unwrap();
+        assert!(result.content.contains("no calendar configured"));
+        assert!(result.content.contains("assumed free (no calendar)"));
+    }
+
+    #[tokio::test]
+    async fn test_replan_reports_displaced_work() {
+        let (_dir, tool) = make_tool();
+        tool.execute(
+            json!({"action": "configure_schedule", "work_start_hour": 9, "work_end_hour": 12}),
+        )
+        .await
+        .unwrap();
+        for (title, priority) in [("Migration", "high"), ("Newsletter", "medium")] {
+            tool.execute(json!({
+                "action": "add_deadline",
+                "title": title,
+                "due_date": upcoming_date_str(4),
+                "priority": priority,
+                "estimated_hours": 1.0
+            }))
+            .await
+            .unwrap();
+        }
+        tool.execute(json!({"action": "schedule"})).await.unwrap();
+
+        let result = tool
+            .execute(json!({"action": "replan", "deadline_id": 1, "remaining_hours": 6.0}))
+            .await
+            .unwrap();
+        assert!(result.content.contains("Replan: #1 'Migration'"));
+        assert!(result.content.contains("1.0h → 6.0h remaining"));
+        assert!(result.content.contains("#2 Newsletter: moved from"));
+    }
+
     #[tokio::test]
     async fn test_unknown_action() {
         let (_dir, tool) = make_tool();
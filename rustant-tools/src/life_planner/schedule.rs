@@ -0,0 +1,642 @@
+//! Slot placement for the `schedule` and `replan` actions.
+//!
+//! Work hours are cut into one-hour slots. Calendar busy blocks are taken
+//! out, pinned habits claim their hour, and open deadlines are placed
+//! earliest-due first into the highest-energy free slots before their due
+//! date. A deadline at or above the habit-override priority may take a
+//! habit's slot when nothing else is left; anything less important is
+//! reported as not fully scheduled instead.
+
+use super::calendar::{BusyBlock, SlotSource};
+use super::energy::energy_score;
+use super::{Deadline, DeadlineStatus, HabitEntry, HabitFrequency, LifePlannerTool, PlannerState};
+use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+/// One entry of a generated schedule.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub(super) struct Placement {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline_id: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub habit_id: Option<usize>,
+    pub title: String,
+    /// Why this slot was chosen.
+    pub reason: String,
+}
+
+/// A pinned habit that gave up its slot to an important deadline.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Displacement {
+    pub habit: String,
+    pub at: NaiveDateTime,
+    pub by_deadline: usize,
+    pub by_title: String,
+}
+
+/// A deadline that did not get all the hours it needs before its due date.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Shortfall {
+    pub deadline_id: usize,
+    pub title: String,
+    pub needed_hours: usize,
+    pub placed_hours: usize,
+    pub due: NaiveDate,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct Plan {
+    pub placements: Vec<Placement>,
+    pub displaced: Vec<Displacement>,
+    pub shortfalls: Vec<Shortfall>,
+    /// Calendar events in the window, shown alongside the placements.
+    pub busy: Vec<BusyBlock>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Occupant {
+    Free,
+    Busy,
+    /// Index into the habits list.
+    Habit(usize),
+    /// Index into the deadlines list, and whether a habit was displaced.
+    Task(usize, bool),
+}
+
+struct Slot {
+    start: NaiveDateTime,
+    energy: f64,
+    basis: &'static str,
+    occupant: Occupant,
+}
+
+/// Build a schedule of `days` days starting at `now`.
+pub(super) fn plan(
+    state: &PlannerState,
+    now: NaiveDateTime,
+    days: u32,
+    busy: &[BusyBlock],
+    source: SlotSource,
+) -> Plan {
+    let prefs = &state.schedule_prefs;
+    let first = next_hour(now);
+    let today = now.date();
+    let last_day = today + Duration::days(i64::from(days.max(1)) - 1);
+
+    let mut slots: Vec<Slot> = Vec::new();
+    for day in today.iter_days().take_while(|d| *d <= last_day) {
+        for hour in prefs.work_start_hour..prefs.work_end_hour.min(24) {
+            let Some(start) = day.and_hms_opt(u32::from(hour), 0, 0) else {
+                continue;
+            };
+            if start < first {
+                continue;
+            }
+            let end = start + Duration::hours(1);
+            let (energy, basis) =
+                energy_score(&state.energy_profile, state.calibration.as_ref(), hour);
+            let occupant = if busy.iter().any(|b| b.start < end && b.end > start) {
+                Occupant::Busy
+            } else {
+                Occupant::Free
+            };
+            slots.push(Slot {
+                start,
+                energy,
+                basis,
+                occupant,
+            });
+        }
+    }
+
+    // Pinned habits outside work hours never compete with tasks; they are
+    // listed but take no slot. Inside work hours a calendar event wins.
+    let mut off_hours_habits = Vec::new();
+    for (index, habit) in state.habits.iter().enumerate() {
+        let Some(hour) = habit.preferred_hour else {
+            continue;
+        };
+        let in_work_hours = (prefs.work_start_hour..prefs.work_end_hour).contains(&hour);
+        for day in habit_days(habit, today, last_day) {
+            let Some(start) = day.and_hms_opt(u32::from(hour), 0, 0) else {
+                continue;
+            };
+            if start < first {
+                continue;
+            }
+            if !in_work_hours {
+                off_hours_habits.push((index, start));
+            } else if let Some(slot) = slots
+                .iter_mut()
+                .find(|s| s.start == start && s.occupant == Occupant::Free)
+            {
+                slot.occupant = Occupant::Habit(index);
+            }
+        }
+    }
+
+    let mut open: Vec<(usize, &Deadline, NaiveDate)> = state
+        .deadlines
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| d.status != DeadlineStatus::Completed)
+        .filter_map(|(i, d)| {
+            NaiveDate::parse_from_str(&d.due_date, "%Y-%m-%d")
+                .ok()
+                .map(|due| (i, d, due))
+        })
+        .collect();
+    open.sort_by_key(|(_, d, due)| (*due, LifePlannerTool::priority_sort_key(&d.priority), d.id));
+
+    let mut result = Plan::default();
+    let may_displace = LifePlannerTool::priority_sort_key(&prefs.habit_override_priority);
+    for (index, deadline, due) in open {
+        let needed = needed_hours(deadline);
+        // Overdue work goes wherever it fits.
+        let latest = if due < today { last_day } else { due };
+
+        let mut chosen = best_slots(&slots, latest, needed, |o| o == Occupant::Free);
+        if chosen.len() < needed
+            && LifePlannerTool::priority_sort_key(&deadline.priority) <= may_displace
+        {
+            let extra = best_slots(&slots, latest, needed - chosen.len(), |o| {
+                matches!(o, Occupant::Habit(_))
+            });
+            for &slot_index in &extra {
+                if let Occupant::Habit(habit) = slots[slot_index].occupant {
+                    result.displaced.push(Displacement {
+                        habit: state.habits[habit].name.clone(),
+                        at: slots[slot_index].start,
+                        by_deadline: deadline.id,
+                        by_title: deadline.title.clone(),
+                    });
+                }
+                slots[slot_index].occupant = Occupant::Task(index, true);
+            }
+            chosen.extend(extra);
+        }
+        for &slot_index in &chosen {
+            if slots[slot_index].occupant == Occupant::Free {
+                slots[slot_index].occupant = Occupant::Task(index, false);
+            }
+        }
+        if chosen.len() < needed {
+            result.shortfalls.push(Shortfall {
+                deadline_id: deadline.id,
+                title: deadline.title.clone(),
+                needed_hours: needed,
+                placed_hours: chosen.len(),
+                due,
+            });
+        }
+    }
+
+    result.placements = placements(state, &slots, source, today);
+    for (index, start) in off_hours_habits {
+        result.placements.push(habit_placement(
+            &state.habits[index],
+            start,
+            "pinned outside work hours",
+        ));
+    }
+    result.placements.sort_by_key(|p| p.start);
+    let window_end = (last_day + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or(now);
+    result.busy = busy
+        .iter()
+        .filter(|b| b.end > first && b.start < window_end)
+        .cloned()
+        .collect();
+    result
+}
+
+/// Hours a deadline needs; unestimated deadlines get one.
+fn needed_hours(deadline: &Deadline) -> usize {
+    if deadline.estimated_hours > 0.0 {
+        deadline.estimated_hours.ceil() as usize
+    } else {
+        1
+    }
+}
+
+/// Indices of up to `count` matching slots on or before `latest`, best
+/// energy first, earlier first among equals.
+fn best_slots(
+    slots: &[Slot],
+    latest: NaiveDate,
+    count: usize,
+    accept: impl Fn(Occupant) -> bool,
+) -> Vec<usize> {
+    let mut candidates: Vec<usize> = (0..slots.len())
+        .filter(|&i| slots[i].start.date() <= latest && accept(slots[i].occupant))
+        .collect();
+    candidates.sort_by(|&a, &b| {
+        slots[b]
+            .energy
+            .total_cmp(&slots[a].energy)
+            .then(slots[a].start.cmp(&slots[b].start))
+    });
+    candidates.truncate(count);
+    candidates
+}
+
+/// Days in `first..=last` on which a habit is due.
+fn habit_days(habit: &HabitEntry, first: NaiveDate, last: NaiveDate) -> Vec<NaiveDate> {
+    let last_done = habit
+        .last_completed
+        .as_deref()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+    let step = match habit.frequency {
+        HabitFrequency::Daily => 1,
+        HabitFrequency::Weekly => 7,
+    };
+    let mut day = match last_done {
+        Some(done) => (done + Duration::days(step)).max(first),
+        None => first,
+    };
+    let mut days = Vec::new();
+    while day <= last {
+        days.push(day);
+        day += Duration::days(step);
+    }
+    days
+}
+
+/// Merge consecutive slots of the same deadline into placements.
+fn placements(
+    state: &PlannerState,
+    slots: &[Slot],
+    source: SlotSource,
+    today: NaiveDate,
+) -> Vec<Placement> {
+    let mut out: Vec<Placement> = Vec::new();
+    let mut run: Vec<&Slot> = Vec::new();
+    let flush = |run: &mut Vec<&Slot>, out: &mut Vec<Placement>| {
+        let Some(first) = run.first() else {
+            return;
+        };
+        let Occupant::Task(index, _) = first.occupant else {
+            return;
+        };
+        let deadline = &state.deadlines[index];
+        let energy = run.iter().map(|s| s.energy).sum::<f64>() / run.len() as f64;
+        let mut reason = format!(
+            "energy {:.2} ({}), {}",
+            energy,
+            first.basis,
+            source.describe()
+        );
+        if run
+            .iter()
+            .any(|s| matches!(s.occupant, Occupant::Task(_, true)))
+        {
+            reason.push_str(", took a habit's slot");
+        }
+        if NaiveDate::parse_from_str(&deadline.due_date, "%Y-%m-%d").is_ok_and(|due| due < today) {
+            reason.push_str(", overdue");
+        }
+        out.push(Placement {
+            start: first.start,
+            end: run[run.len() - 1].start + Duration::hours(1),
+            deadline_id: Some(deadline.id),
+            habit_id: None,
+            title: deadline.title.clone(),
+            reason,
+        });
+        run.clear();
+    };
+
+    for slot in slots {
+        match slot.occupant {
+            Occupant::Task(index, _) => {
+                let continues = run.last().is_some_and(|prev| {
+                    matches!(prev.occupant, Occupant::Task(i, _) if i == index)
+                        && prev.start + Duration::hours(1) == slot.start
+                });
+                if !continues {
+                    flush(&mut run, &mut out);
+                }
+                run.push(slot);
+            }
+            Occupant::Habit(index) => {
+                flush(&mut run, &mut out);
+                out.push(habit_placement(
+                    &state.habits[index],
+                    slot.start,
+                    "pinned habit",
+                ));
+            }
+            Occupant::Free | Occupant::Busy => flush(&mut run, &mut out),
+        }
+    }
+    flush(&mut run, &mut out);
+    out
+}
+
+fn habit_placement(habit: &HabitEntry, start: NaiveDateTime, reason: &str) -> Placement {
+    Placement {
+        start,
+        end: start + Duration::minutes(i64::from(habit.duration_mins)),
+        deadline_id: None,
+        habit_id: Some(habit.id),
+        title: habit.name.clone(),
+        reason: reason.to_string(),
+    }
+}
+
+fn next_hour(now: NaiveDateTime) -> NaiveDateTime {
+    let hour = now
+        .with_minute(0)
+        .and_then(|t| t.with_second(0))
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or(now);
+    if hour == now {
+        hour
+    } else {
+        hour + Duration::hours(1)
+    }
+}
+
+/// Render a plan, grouped by day.
+pub(super) fn render(plan: &Plan, state: &PlannerState) -> String {
+    let mut entries: Vec<(NaiveDateTime, String)> = plan
+        .placements
+        .iter()
+        .map(|placement| {
+            let label = match (placement.deadline_id, placement.habit_id) {
+                (Some(id), _) => {
+                    let priority = state
+                        .deadlines
+                        .iter()
+                        .find(|d| d.id == id)
+                        .map(|d| format!(" [{}]", d.priority))
+                        .unwrap_or_default();
+                    format!("#{} {}{}", id, placement.title, priority)
+                }
+                _ => format!("habit: {}", placement.title),
+            };
+            let line = format!(
+                "  {}–{}  {} — {}\n",
+                placement.start.format("%H:%M"),
+                placement.end.format("%H:%M"),
+                label,
+                placement.reason
+            );
+            (placement.start, line)
+        })
+        .collect();
+    entries.extend(plan.busy.iter().map(|block| {
+        let line = format!(
+            "  {}–{}  busy: {}\n",
+            block.start.format("%H:%M"),
+            block.end.format("%H:%M"),
+            block.title
+        );
+        (block.start, line)
+    }));
+    entries.sort_by_key(|(start, _)| *start);
+
+    let mut out = String::new();
+    let mut current_day = None;
+    for (start, line) in entries {
+        let day = start.date();
+        if current_day != Some(day) {
+            out.push_str(&format!("\n{}\n", day.format("%a %Y-%m-%d")));
+            current_day = Some(day);
+        }
+        out.push_str(&line);
+    }
+    if plan.placements.is_empty() {
+        out.push_str("\nNothing to schedule.\n");
+    }
+    if !plan.displaced.is_empty() {
+        out.push_str("\nDisplaced habits:\n");
+        for d in &plan.displaced {
+            out.push_str(&format!(
+                "  - {} on {} by #{} {}\n",
+                d.habit,
+                d.at.format("%a %Y-%m-%d %H:%M"),
+                d.by_deadline,
+                d.by_title
+            ));
+        }
+    }
+    if !plan.shortfalls.is_empty() {
+        out.push_str("\nNot fully scheduled:\n");
+        for s in &plan.shortfalls {
+            out.push_str(&format!(
+                "  - #{} {}: {}h placed of {}h needed before {}\n",
+                s.deadline_id, s.title, s.placed_hours, s.needed_hours, s.due
+            ));
+        }
+    }
+    out
+}
+
+/// Describe how deadlines moved between two schedules, from `now` on.
+pub(super) fn changes(before: &[Placement], after: &Plan, now: NaiveDateTime) -> Vec<String> {
+    let starts = |placements: &[Placement], id: usize| -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        placements
+            .iter()
+            .filter(|p| p.deadline_id == Some(id) && p.end > now)
+            .map(|p| (p.start, p.end))
+            .collect()
+    };
+    let mut ids: Vec<usize> = before.iter().filter_map(|p| p.deadline_id).collect();
+    ids.sort_unstable();
+    ids.dedup();
+
+    let mut lines = Vec::new();
+    for id in ids {
+        let old = starts(before, id);
+        if old.is_empty() {
+            continue;
+        }
+        let new = starts(&after.placements, id);
+        if old == new {
+            continue;
+        }
+        let title = before
+            .iter()
+            .find(|p| p.deadline_id == Some(id))
+            .map(|p| p.title.as_str())
+            .unwrap_or_default();
+        let fmt = |blocks: &[(NaiveDateTime, NaiveDateTime)]| {
+            blocks
+                .iter()
+                .map(|(s, e)| format!("{}–{}", s.format("%a %H:%M"), e.format("%H:%M")))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        if new.is_empty() {
+            lines.push(format!("#{} {}: dropped from {}", id, title, fmt(&old)));
+        } else {
+            lines.push(format!(
+                "#{} {}: moved from {} to {}",
+                id,
+                title,
+                fmt(&old),
+                fmt(&new)
+            ));
+        }
+    }
+    for d in &after.displaced {
+        lines.push(format!(
+            "habit {} on {} displaced by #{} {}",
+            d.habit,
+            d.at.format("%a %H:%M"),
+            d.by_deadline,
+            d.by_title
+        ));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{DeadlinePriority, SchedulePrefs};
+    use super::*;
+    use chrono::Utc;
+
+    fn at(date: &str, hour: u32) -> NaiveDateTime {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn deadline(id: usize, due: &str, priority: DeadlinePriority, hours: f64) -> Deadline {
+        Deadline {
+            id,
+            title: format!("Task {}", id),
+            due_date: due.into(),
+            priority,
+            estimated_hours: hours,
+            category: "general".into(),
+            status: DeadlineStatus::Pending,
+            notes: String::new(),
+            created_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+
+    fn habit(id: usize, hour: u8) -> HabitEntry {
+        HabitEntry {
+            id,
+            name: "Exercise".into(),
+            frequency: HabitFrequency::Daily,
+            streak: 0,
+            best_streak: 0,
+            last_completed: None,
+            target_streak: 30,
+            history: Vec::new(),
+            created_at: Utc::now(),
+            preferred_hour: Some(hour),
+            duration_mins: 30,
+        }
+    }
+
+    fn state() -> PlannerState {
+        PlannerState {
+            schedule_prefs: SchedulePrefs {
+                work_start_hour: 9,
+                work_end_hour: 12,
+                ..SchedulePrefs::default()
+            },
+            ..PlannerState::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_skips_busy_slots_and_prefers_energy() {
+        let mut state = state();
+        state
+            .deadlines
+            .push(deadline(1, "2026-10-19", DeadlinePriority::High, 2.0));
+        let busy = vec![BusyBlock {
+            start: at("2026-10-19", 9),
+            end: at("2026-10-19", 10),
+            title: "Standup".into(),
+        }];
+
+        let plan = plan(&state, at("2026-10-19", 8), 1, &busy, SlotSource::Ics);
+        assert_eq!(plan.placements.len(), 1);
+        let placement = &plan.placements[0];
+        assert_eq!(
+            (placement.start, placement.end),
+            (at("2026-10-19", 10), at("2026-10-19", 12))
+        );
+        assert_eq!(
+            placement.reason,
+            "energy 1.00 (profile), free in ICS calendar"
+        );
+        assert!(plan.shortfalls.is_empty());
+    }
+
+    #[test]
+    fn test_low_priority_task_does_not_displace_habit() {
+        let mut state = state();
+        state.habits.push(habit(1, 10));
+        state
+            .deadlines
+            .push(deadline(1, "2026-10-19", DeadlinePriority::Low, 3.0));
+
+        let plan = plan(&state, at("2026-10-19", 8), 1, &[], SlotSource::Unchecked);
+        assert!(plan.displaced.is_empty());
+        assert_eq!(
+            plan.shortfalls,
+            vec![Shortfall {
+                deadline_id: 1,
+                title: "Task 1".into(),
+                needed_hours: 3,
+                placed_hours: 2,
+                due: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+            }]
+        );
+        assert!(
+            plan.placements
+                .iter()
+                .any(|p| p.habit_id == Some(1) && p.start == at("2026-10-19", 10))
+        );
+    }
+
+    #[test]
+    fn test_urgent_task_displaces_habit() {
+        let mut state = state();
+        state.habits.push(habit(1, 10));
+        state
+            .deadlines
+            .push(deadline(1, "2026-10-19", DeadlinePriority::Critical, 3.0));
+
+        let plan = plan(&state, at("2026-10-19", 8), 1, &[], SlotSource::Unchecked);
+        assert_eq!(plan.displaced.len(), 1);
+        assert_eq!(plan.displaced[0].at, at("2026-10-19", 10));
+        assert!(plan.shortfalls.is_empty());
+        assert_eq!(plan.placements.len(), 1);
+        assert!(plan.placements[0].reason.contains("took a habit's slot"));
+    }
+
+    #[test]
+    fn test_changes_report_moved_deadlines() {
+        let mut state = state();
+        state
+            .deadlines
+            .push(deadline(1, "2026-10-19", DeadlinePriority::High, 1.0));
+        state
+            .deadlines
+            .push(deadline(2, "2026-10-20", DeadlinePriority::Medium, 1.0));
+        let now = at("2026-10-19", 8);
+        let before = plan(&state, now, 2, &[], SlotSource::Unchecked);
+
+        // Deadline 1 slips and now needs the whole morning.
+        state.deadlines[0].estimated_hours = 3.0;
+        let after = plan(&state, now, 2, &[], SlotSource::Unchecked);
+        let lines = changes(&before.placements, &after, now);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("#1 Task 1: moved from Mon 09:00–10:00 to Mon 09:00–12:00"));
+        assert!(lines[1].starts_with("#2 Task 2: moved from Mon 10:00–11:00 to Tue 09:00–10:00"));
+    }
+}
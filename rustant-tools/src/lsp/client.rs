@@ -4,9 +4,15 @@
 //! Content-Length framed stdin/stdout transport. It can start a language server
 //! process, send requests and notifications, and handle server-initiated
 //! notifications such as `textDocument/publishDiagnostics`.
+//!
+//! Proposed file contents can be checked before they are written: an overlay
+//! shows the server in-memory text for a document (`didOpen`, or `didChange`
+//! with a new version if it is already open) and its diagnostics are collected
+//! without touching disk.
 
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::time::Duration;
 
 use serde_json::json;
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
@@ -74,6 +80,11 @@ pub struct LspClient {
     /// Diagnostics received from `textDocument/publishDiagnostics` notifications,
     /// keyed by document URI.
     cached_diagnostics: HashMap<String, Vec<Diagnostic>>,
+    /// Document version the cached diagnostics were published for, when the
+    /// server reported one.
+    diagnostic_versions: HashMap<String, i32>,
+    /// Current version of each open document.
+    document_versions: HashMap<String, i32>,
     root_uri: String,
 }
 
@@ -124,6 +135,8 @@ impl LspClient {
             initialized: false,
             open_documents: HashSet::new(),
             cached_diagnostics: HashMap::new(),
+            diagnostic_versions: HashMap::new(),
+            document_versions: HashMap::new(),
             root_uri,
         };
 
@@ -325,6 +338,15 @@ impl LspClient {
                         count = diag_params.diagnostics.len(),
                         "Received diagnostics"
                     );
+                    match diag_params.version {
+                        Some(version) => {
+                            self.diagnostic_versions
+                                .insert(diag_params.uri.clone(), version);
+                        }
+                        None => {
+                            self.diagnostic_versions.remove(&diag_params.uri);
+                        }
+                    }
                     self.cached_diagnostics
                         .insert(diag_params.uri, diag_params.diagnostics);
                 }
@@ -376,9 +398,143 @@ impl LspClient {
         .await?;
 
         self.open_documents.insert(uri.clone());
+        self.document_versions.insert(uri.clone(), 1);
         Ok(uri)
     }
 
+    /// Show the server `text` as the contents of `file` without writing it.
+    ///
+    /// Opens the document with `text` if it is not open yet, otherwise sends
+    /// the full text as a new version via `textDocument/didChange`. The file
+    /// need not exist. Cached diagnostics for the document are dropped so
+    /// that only ones published for the overlay are seen. Returns the URI and
+    /// the overlay's version.
+    pub async fn open_overlay(
+        &mut self,
+        file: &Path,
+        text: &str,
+    ) -> Result<(String, i32), LspError> {
+        let uri = overlay_uri(file)?;
+
+        let current = self
+            .document_versions
+            .get(&uri)
+            .copied()
+            .filter(|_| self.open_documents.contains(&uri));
+        let version = match current {
+            Some(current) => {
+                let version = current + 1;
+                self.send_notification(
+                    "textDocument/didChange",
+                    json!({
+                        "textDocument": { "uri": uri, "version": version },
+                        "contentChanges": [{ "text": text }]
+                    }),
+                )
+                .await?;
+                version
+            }
+            None => {
+                let text_doc = TextDocumentItem {
+                    uri: uri.clone(),
+                    language_id: detect_language_id(file),
+                    version: 1,
+                    text: text.to_string(),
+                };
+                self.send_notification("textDocument/didOpen", json!({ "textDocument": text_doc }))
+                    .await?;
+                self.open_documents.insert(uri.clone());
+                1
+            }
+        };
+
+        self.document_versions.insert(uri.clone(), version);
+        self.cached_diagnostics.remove(&uri);
+        self.diagnostic_versions.remove(&uri);
+        Ok((uri, version))
+    }
+
+    /// Drop the overlay for `file` with `textDocument/didClose`.
+    ///
+    /// The server falls back to the file on disk, and the next request for
+    /// the file reopens it from there.
+    pub async fn close_overlay(&mut self, file: &Path) -> Result<(), LspError> {
+        let uri = overlay_uri(file)?;
+        if !self.open_documents.remove(&uri) {
+            return Ok(());
+        }
+        self.document_versions.remove(&uri);
+        self.cached_diagnostics.remove(&uri);
+        self.diagnostic_versions.remove(&uri);
+        self.send_notification(
+            "textDocument/didClose",
+            json!({ "textDocument": { "uri": uri } }),
+        )
+        .await
+    }
+
+    /// Diagnostics the server reports for `text` as the contents of `file`,
+    /// without touching disk.
+    ///
+    /// Waits at most `budget` for the server to publish diagnostics for the
+    /// overlay and returns [`LspError::Timeout`] if it does not. The overlay
+    /// is closed either way.
+    pub async fn overlay_diagnostics(
+        &mut self,
+        file: &Path,
+        text: &str,
+        budget: Duration,
+    ) -> Result<Vec<Diagnostic>, LspError> {
+        let deadline = tokio::time::Instant::now() + budget;
+        let (uri, version) = self.open_overlay(file, text).await?;
+        let result = self.wait_for_diagnostics(&uri, version, deadline).await;
+        if let Err(e) = self.close_overlay(file).await {
+            tracing::warn!(error = %e, uri = %uri, "Failed to close LSP overlay");
+        }
+        result.map_err(|e| match e {
+            LspError::Timeout { .. } => LspError::Timeout {
+                timeout_secs: budget.as_secs_f64().ceil() as u64,
+            },
+            other => other,
+        })
+    }
+
+    /// Read server messages until diagnostics for `version` of `uri` arrive
+    /// or `deadline` passes.
+    ///
+    /// Waiting only ever covers filling the read buffer, which loses nothing
+    /// when it times out; a message that has started arriving is read whole.
+    async fn wait_for_diagnostics(
+        &mut self,
+        uri: &str,
+        version: i32,
+        deadline: tokio::time::Instant,
+    ) -> Result<Vec<Diagnostic>, LspError> {
+        loop {
+            if let Some(diagnostics) = self.cached_diagnostics.get(uri)
+                && diagnostics_are_current(self.diagnostic_versions.get(uri).copied(), version)
+            {
+                return Ok(diagnostics.clone());
+            }
+
+            match tokio::time::timeout_at(deadline, self.stdout.fill_buf()).await {
+                Err(_) => return Err(LspError::Timeout { timeout_secs: 0 }),
+                Ok(Err(e)) => return Err(e.into()),
+                Ok(Ok([])) => {
+                    return Err(LspError::ProtocolError {
+                        message: "Server closed its output while waiting for diagnostics".into(),
+                    });
+                }
+                Ok(Ok(_)) => {}
+            }
+
+            let msg = self.read_message().await?;
+            if msg.get("method").is_some() {
+                self.handle_notification(&msg);
+            }
+        }
+    }
+
     // ------------------------------------------------------------------
     // High-level LSP operations
     // ------------------------------------------------------------------
@@ -596,6 +752,8 @@ impl LspClient {
 #[derive(serde::Deserialize)]
 struct PublishDiagnosticsNotification {
     uri: String,
+    #[serde(default)]
+    version: Option<i32>,
     diagnostics: Vec<Diagnostic>,
 }
 
@@ -614,6 +772,32 @@ pub fn file_path_to_uri(path: &Path) -> Result<String, LspError> {
     Ok(format!("file://{}", canonical.display()))
 }
 
+/// The `file://` URI for a file that may not exist yet.
+///
+/// Existing files are canonicalized like [`file_path_to_uri`]; for new files
+/// the parent directory is.
+fn overlay_uri(path: &Path) -> Result<String, LspError> {
+    if let Ok(uri) = file_path_to_uri(path) {
+        return Ok(uri);
+    }
+    let not_found = || LspError::FileNotFound {
+        path: path.display().to_string(),
+    };
+    let name = path.file_name().ok_or_else(not_found)?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let parent = std::fs::canonicalize(parent).map_err(|_| not_found())?;
+    Ok(format!("file://{}", parent.join(name).display()))
+}
+
+/// Whether diagnostics published for `published` describe document
+/// `version`. Servers that omit the version are taken at their word.
+fn diagnostics_are_current(published: Option<i32>, version: i32) -> bool {
+    published.is_none_or(|published| published >= version)
+}
+
 /// Build `textDocument/hover`-style positional params as a `serde_json::Value`.
 fn make_text_document_position_params(uri: &str, line: u32, character: u32) -> serde_json::Value {
     json!({
@@ -1191,6 +1375,43 @@ mod tests {
         assert_eq!(parsed.diagnostics[0].message, "syntax error");
     }
 
+    #[test]
+    fn test_publish_diagnostics_version() {
+        let parsed: PublishDiagnosticsNotification = serde_json::from_value(json!({
+            "uri": "file:///project/src/lib.rs",
+            "version": 3,
+            "diagnostics": []
+        }))
+        .unwrap();
+        assert_eq!(parsed.version, Some(3));
+
+        assert!(diagnostics_are_current(Some(3), 3));
+        assert!(diagnostics_are_current(None, 3));
+        assert!(!diagnostics_are_current(Some(2), 3));
+    }
+
+    // ------------------------------------------------------------------
+    // Overlays
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_overlay_uri_for_new_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let existing = dir.path().join("lib.rs");
+        std::fs::write(&existing, "").unwrap();
+        assert_eq!(
+            overlay_uri(&existing).unwrap(),
+            file_path_to_uri(&existing).unwrap()
+        );
+
+        let canonical = std::fs::canonicalize(dir.path()).unwrap();
+        assert_eq!(
+            overlay_uri(&dir.path().join("new.rs")).unwrap(),
+            format!("file://{}", canonical.join("new.rs").display())
+        );
+        assert!(overlay_uri(&dir.path().join("missing/new.rs")).is_err());
+    }
+
     // ------------------------------------------------------------------
     // parse_location_response edge cases
     // ------------------------------------------------------------------
@@ -15,6 +15,7 @@
 
 pub mod client;
 pub mod discovery;
+pub mod pre_write;
 pub mod tools;
 pub mod types;
 
@@ -24,6 +25,7 @@ use discovery::ServerRegistry;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 use tracing::{info, warn};
 use types::{CompletionItem, Diagnostic, Location, TextEdit, WorkspaceEdit};
@@ -163,6 +165,29 @@ impl LspManager {
         }
     }
 
+    /// Diagnostics for `text` as the proposed contents of `file`, collected
+    /// from an in-memory overlay without writing it.
+    ///
+    /// Waits at most `budget` for the language server, including starting
+    /// it; [`LspError::Timeout`] means the budget ran out.
+    pub async fn diagnostics_for_content(
+        &self,
+        file: &Path,
+        text: &str,
+        budget: Duration,
+    ) -> Result<Vec<Diagnostic>, LspError> {
+        let deadline = tokio::time::Instant::now() + budget;
+        let language = self.get_client_for_file(file).await?;
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        let mut clients = self.clients.lock().await;
+        let client = clients
+            .get_mut(&language)
+            .ok_or_else(|| LspError::ServerNotRunning {
+                language: language.clone(),
+            })?;
+        client.overlay_diagnostics(file, text, remaining).await
+    }
+
     /// Extract hover text from the raw hover result.
     fn extract_hover_text(hover: &types::HoverResult) -> String {
         match &hover.contents {
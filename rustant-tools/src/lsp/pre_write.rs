@@ -0,0 +1,391 @@
+//! Pre-write diagnostics for code written by `file_write` and `smart_edit`.
+//!
+//! [`PreWriteCheck`] shows the proposed contents of a file to its language
+//! server as an in-memory overlay before the write happens. Errors the file
+//! does not already have are returned to the model as a correction prompt
+//! instead of writing. After `max_attempts` rejected writes to one file, or
+//! when the server does not answer within the time budget, the write goes
+//! ahead with a note.
+
+use super::LspManager;
+use super::client::LspError;
+use super::discovery::ServerRegistry;
+use super::types::{Diagnostic, DiagnosticSeverity};
+use async_trait::async_trait;
+use rustant_core::config::PreWriteDiagnosticsConfig;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Most errors listed in a correction prompt or note.
+const MAX_LISTED_ERRORS: usize = 10;
+
+/// Source of diagnostics for file contents that are not on disk.
+#[async_trait]
+pub trait OverlayDiagnostics: Send + Sync {
+    /// Diagnostics for `text` as the contents of `file`, waiting at most `budget`.
+    async fn diagnostics_for_content(
+        &self,
+        file: &Path,
+        text: &str,
+        budget: Duration,
+    ) -> Result<Vec<Diagnostic>, LspError>;
+}
+
+#[async_trait]
+impl OverlayDiagnostics for LspManager {
+    async fn diagnostics_for_content(
+        &self,
+        file: &Path,
+        text: &str,
+        budget: Duration,
+    ) -> Result<Vec<Diagnostic>, LspError> {
+        LspManager::diagnostics_for_content(self, file, text, budget).await
+    }
+}
+
+/// What a write tool should do with proposed contents.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreWriteOutcome {
+    /// Write the file, adding `note` to the tool output when set.
+    Write { note: Option<String> },
+    /// Do not write; return `prompt` to the model as an error.
+    Reject { prompt: String },
+}
+
+/// Counters for judging whether the check pays for its latency.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PreWriteStats {
+    /// Writes the language server answered for.
+    pub checked: u64,
+    /// Writes rejected because of new errors.
+    pub rejected: u64,
+    /// New errors caught before they reached disk.
+    pub errors_caught: u64,
+    /// New errors written anyway once `max_attempts` ran out.
+    pub errors_written: u64,
+    /// Writes that went ahead because the budget ran out.
+    pub timed_out: u64,
+    /// Total time spent waiting for diagnostics, in milliseconds.
+    pub wait_ms: u64,
+}
+
+#[derive(Default)]
+struct CheckState {
+    /// Consecutive rejected writes per file.
+    attempts: HashMap<PathBuf, u32>,
+    stats: PreWriteStats,
+}
+
+/// Checks proposed file contents against a language server before writing.
+pub struct PreWriteCheck {
+    backend: Arc<dyn OverlayDiagnostics>,
+    languages: Vec<String>,
+    budget: Duration,
+    max_attempts: u32,
+    state: Mutex<CheckState>,
+}
+
+impl PreWriteCheck {
+    pub fn new(backend: Arc<dyn OverlayDiagnostics>, config: &PreWriteDiagnosticsConfig) -> Self {
+        Self {
+            backend,
+            languages: config.languages.clone(),
+            budget: Duration::from_millis(config.budget_ms),
+            max_attempts: config.max_attempts,
+            state: Mutex::new(CheckState::default()),
+        }
+    }
+
+    /// Whether writes to `path` are checked.
+    pub fn applies_to(&self, path: &Path) -> bool {
+        ServerRegistry::detect_language(path).is_some_and(|lang| self.languages.contains(&lang))
+    }
+
+    pub fn stats(&self) -> PreWriteStats {
+        self.state.lock().unwrap().stats
+    }
+
+    /// Decide whether `proposed` may be written to `path`; `display` is the
+    /// path as the model named it.
+    pub async fn review(&self, path: &Path, display: &str, proposed: &str) -> PreWriteOutcome {
+        if !self.applies_to(path) {
+            return PreWriteOutcome::Write { note: None };
+        }
+        let started = Instant::now();
+        let result = self.new_errors(path, proposed).await;
+        let waited = started.elapsed().as_millis() as u64;
+
+        let mut state = self.state.lock().unwrap();
+        state.stats.wait_ms += waited;
+        let errors = match result {
+            Ok(errors) => errors,
+            Err(LspError::Timeout { .. }) => {
+                state.stats.timed_out += 1;
+                return PreWriteOutcome::Write {
+                    note: Some(format!(
+                        "Note: written without pre-write diagnostics; the language server \
+                         did not answer within {}ms.",
+                        self.budget.as_millis()
+                    )),
+                };
+            }
+            Err(e) => {
+                debug!(path = %path.display(), error = %e, "Pre-write diagnostics unavailable");
+                return PreWriteOutcome::Write { note: None };
+            }
+        };
+        state.stats.checked += 1;
+        debug!(path = %path.display(), errors = errors.len(), waited_ms = waited, "Pre-write diagnostics");
+
+        if errors.is_empty() {
+            state.attempts.remove(path);
+            return PreWriteOutcome::Write { note: None };
+        }
+        let attempt = {
+            let attempts = state.attempts.entry(path.to_path_buf()).or_insert(0);
+            *attempts += 1;
+            *attempts
+        };
+        if attempt > self.max_attempts {
+            state.attempts.remove(path);
+            state.stats.errors_written += errors.len() as u64;
+            return PreWriteOutcome::Write {
+                note: Some(format!(
+                    "Note: written after {} rejected attempts; the language server still \
+                     reports {} new error(s):\n{}",
+                    self.max_attempts,
+                    errors.len(),
+                    list_errors(&errors)
+                )),
+            };
+        }
+        state.stats.rejected += 1;
+        state.stats.errors_caught += errors.len() as u64;
+        PreWriteOutcome::Reject {
+            prompt: format!(
+                "Not written: the language server reports {} new error(s) in '{}':\n{}\n\
+                 Fix the code and write it again (attempt {} of {}).",
+                errors.len(),
+                display,
+                list_errors(&errors),
+                attempt,
+                self.max_attempts
+            ),
+        }
+    }
+
+    /// Errors in `proposed` that the current contents of `path` do not have.
+    async fn new_errors(&self, path: &Path, proposed: &str) -> Result<Vec<Diagnostic>, LspError> {
+        let deadline = tokio::time::Instant::now() + self.budget;
+        let mut errors = errors_only(self.diagnose(path, proposed, deadline).await?);
+        if errors.is_empty() {
+            return Ok(errors);
+        }
+        let Ok(current) = tokio::fs::read_to_string(path).await else {
+            return Ok(errors);
+        };
+        // Line numbers shift with the edit, so existing errors match by message.
+        let mut existing: Vec<String> = errors_only(self.diagnose(path, &current, deadline).await?)
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        errors.retain(|d| match existing.iter().position(|m| *m == d.message) {
+            Some(i) => {
+                existing.swap_remove(i);
+                false
+            }
+            None => true,
+        });
+        Ok(errors)
+    }
+
+    async fn diagnose(
+        &self,
+        path: &Path,
+        text: &str,
+        deadline: tokio::time::Instant,
+    ) -> Result<Vec<Diagnostic>, LspError> {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        let timeout = LspError::Timeout {
+            timeout_secs: self.budget.as_secs(),
+        };
+        if remaining.is_zero() {
+            return Err(timeout);
+        }
+        tokio::time::timeout_at(
+            deadline,
+            self.backend.diagnostics_for_content(path, text, remaining),
+        )
+        .await
+        .map_err(|_| timeout)?
+    }
+}
+
+fn errors_only(diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    diagnostics
+        .into_iter()
+        .filter(|d| d.severity == Some(DiagnosticSeverity::Error))
+        .collect()
+}
+
+fn list_errors(errors: &[Diagnostic]) -> String {
+    let mut lines: Vec<String> = errors
+        .iter()
+        .take(MAX_LISTED_ERRORS)
+        .map(|d| format!("- line {}: {}", d.range.start.line + 1, d.message))
+        .collect();
+    if errors.len() > MAX_LISTED_ERRORS {
+        lines.push(format!(
+            "- ... and {} more",
+            errors.len() - MAX_LISTED_ERRORS
+        ));
+    }
+    lines.join("\n")
+}
+
+static PRE_WRITE_CHECK: OnceLock<Arc<PreWriteCheck>> = OnceLock::new();
+
+/// Check writes by `file_write` and `smart_edit` with `check`.
+///
+/// Called once at startup; later calls are ignored.
+pub fn set_pre_write_check(check: Arc<PreWriteCheck>) {
+    let _ = PRE_WRITE_CHECK.set(check);
+}
+
+/// The check installed with [`set_pre_write_check`], if any.
+pub fn pre_write_check() -> Option<Arc<PreWriteCheck>> {
+    PRE_WRITE_CHECK.get().cloned()
+}
+
+/// Install a check backed by language servers for `workspace` when
+/// `[tools.pre_write_diagnostics]` enables any language.
+pub fn install_pre_write_check(config: &PreWriteDiagnosticsConfig, workspace: &Path) {
+    if config.languages.is_empty() {
+        return;
+    }
+    let manager = Arc::new(LspManager::new(workspace.to_path_buf()));
+    set_pre_write_check(Arc::new(PreWriteCheck::new(manager, config)));
+}
+
+#[cfg(test)]
+struct MarkerBackend {
+    delay: Duration,
+}
+
+#[cfg(test)]
+#[async_trait]
+impl OverlayDiagnostics for MarkerBackend {
+    async fn diagnostics_for_content(
+        &self,
+        _file: &Path,
+        text: &str,
+        _budget: Duration,
+    ) -> Result<Vec<Diagnostic>, LspError> {
+        tokio::time::sleep(self.delay).await;
+        Ok(text
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| l.contains("BROKEN"))
+            .map(|(i, l)| marker_diagnostic(i as u32, l.trim()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+fn marker_diagnostic(line: u32, message: &str) -> Diagnostic {
+    let pos = super::types::Position { line, character: 0 };
+    Diagnostic {
+        range: super::types::Range {
+            start: pos,
+            end: pos,
+        },
+        severity: Some(DiagnosticSeverity::Error),
+        message: message.to_string(),
+        source: None,
+        code: None,
+    }
+}
+
+/// A check for Rust files that reports every line containing `BROKEN` as an
+/// error, answering after `delay`.
+#[cfg(test)]
+pub(crate) fn marker_check(max_attempts: u32, delay: Duration) -> PreWriteCheck {
+    let config = PreWriteDiagnosticsConfig {
+        languages: vec!["rust".into()],
+        budget_ms: 200,
+        max_attempts,
+    };
+    PreWriteCheck::new(Arc::new(MarkerBackend { delay }), &config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_new_errors_rejected_until_attempts_run_out() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("lib.rs");
+        let check = marker_check(2, Duration::ZERO);
+
+        let proposed = "fn main() {\n    BROKEN call\n}\n";
+        for attempt in 1..=2 {
+            let PreWriteOutcome::Reject { prompt } = check.review(&path, "lib.rs", proposed).await
+            else {
+                panic!("attempt {} should be rejected", attempt);
+            };
+            assert!(prompt.contains("- line 2: BROKEN call"), "{}", prompt);
+            assert!(prompt.contains(&format!("attempt {} of 2", attempt)));
+        }
+        let PreWriteOutcome::Write { note: Some(note) } =
+            check.review(&path, "lib.rs", proposed).await
+        else {
+            panic!("third write should go through with a note");
+        };
+        assert!(note.contains("BROKEN call"));
+
+        let stats = check.stats();
+        assert_eq!((stats.rejected, stats.errors_caught), (2, 2));
+        assert_eq!(stats.errors_written, 1);
+    }
+
+    #[tokio::test]
+    async fn test_existing_errors_and_other_languages_pass() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, "BROKEN old\n").unwrap();
+        let check = marker_check(3, Duration::ZERO);
+
+        // The error was already there; moving it does not block the write.
+        assert_eq!(
+            check
+                .review(&path, "lib.rs", "fn a() {}\nBROKEN old\n")
+                .await,
+            PreWriteOutcome::Write { note: None }
+        );
+        assert_eq!(
+            check
+                .review(&dir.path().join("notes.py"), "notes.py", "BROKEN\n")
+                .await,
+            PreWriteOutcome::Write { note: None }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_budget_exceeded_writes_with_note() {
+        let dir = TempDir::new().unwrap();
+        let check = marker_check(3, Duration::from_secs(5));
+        let PreWriteOutcome::Write { note: Some(note) } = check
+            .review(&dir.path().join("lib.rs"), "lib.rs", "BROKEN\n")
+            .await
+        else {
+            panic!("a slow server should not block the write");
+        };
+        assert!(note.contains("200ms"), "{}", note);
+        assert_eq!(check.stats().timed_out, 1);
+    }
+}
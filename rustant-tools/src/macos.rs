@@ -5,12 +5,16 @@
 //! by bridging to native macOS apps via AppleScript and CLI commands.
 //! macOS only.
 
+use crate::mail_triage::{self, BulkOperation, BulkPlan, MailFilter, MailboxRef};
 use crate::registry::Tool;
 use async_trait::async_trait;
+use rustant_core::clipboard::{ClipKind, ClipboardHistory};
 use rustant_core::error::ToolError;
-use rustant_core::types::{RiskLevel, ToolOutput};
+use rustant_core::types::{Attachment, ProgressUpdate, RiskLevel, ToolOutput};
 use serde_json::json;
+use std::path::PathBuf;
 use std::time::Duration;
+use tokio::sync::mpsc;
 use tracing::debug;
 
 // ── Shared Helpers ──────────────────────────────────────────────────────────
@@ -290,6 +294,44 @@ end tell"#
     }
 }
 
+/// Timed Calendar.app events from now until `days` days ahead, as local
+/// `(start, end, title)` triples. All-day events are left out. Used by the
+/// life planner to find busy time.
+pub(crate) async fn calendar_busy_events(
+    days: u64,
+) -> Result<Vec<(chrono::NaiveDateTime, chrono::NaiveDateTime, String)>, String> {
+    let script = format!(
+        r#"on stamp(d)
+    return ((year of d) as string) & "-" & ((month of d as integer) as string) & "-" & ((day of d) as string) & " " & ((hours of d) as string) & ":" & ((minutes of d) as string)
+end stamp
+tell application "Calendar"
+    set output to ""
+    set windowStart to current date
+    set windowEnd to windowStart + ({days} * days)
+    repeat with cal in calendars
+        set calEvents to (every event of cal whose start date < windowEnd and end date > windowStart)
+        repeat with evt in calEvents
+            if allday event of evt is false then
+                set output to output & my stamp(start date of evt) & "|" & my stamp(end date of evt) & "|" & (summary of evt) & linefeed
+            end if
+        end repeat
+    end repeat
+    return output
+end tell"#
+    );
+    let output = run_osascript(&script).await?;
+    let parse = |s: &str| chrono::NaiveDateTime::parse_from_str(s.trim(), "%Y-%m-%d %H:%M").ok();
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '|');
+            let start = parse(parts.next()?)?;
+            let end = parse(parts.next()?)?;
+            Some((start, end, parts.next().unwrap_or("").trim().to_string()))
+        })
+        .collect())
+}
+
 // ── 2. Reminders Tool ───────────────────────────────────────────────────────
 
 pub struct MacosRemindersTool;
@@ -672,7 +714,7 @@ pub struct MacosAppControlTool;
 #[async_trait]
 impl Tool for MacosAppControlTool {
     fn name(&self) -> &str {
-        "macos_app_control"
+        "app_control"
     }
 
     fn description(&self) -> &str {
@@ -699,30 +741,30 @@ impl Tool for MacosAppControlTool {
     }
 
     async fn execute(&self, args: serde_json::Value) -> Result<ToolOutput, ToolError> {
-        let action = require_str(&args, "action", "macos_app_control")?;
+        let action = require_str(&args, "action", "app_control")?;
 
         match action {
             "open" => {
-                let app = require_str(&args, "app_name", "macos_app_control")?;
+                let app = require_str(&args, "app_name", "app_control")?;
                 let safe_app = sanitize_applescript_string(app);
                 debug!(app = %safe_app, "Opening application");
                 run_command("open", &["-a", app]).await.map_err(|e| {
                     ToolError::ExecutionFailed {
-                        name: "macos_app_control".into(),
+                        name: "app_control".into(),
                         message: e,
                     }
                 })?;
                 Ok(ToolOutput::text(format!("Opened '{app}'.")))
             }
             "quit" => {
-                let app = require_str(&args, "app_name", "macos_app_control")?;
+                let app = require_str(&args, "app_name", "app_control")?;
                 let safe_app = sanitize_applescript_string(app);
                 debug!(app = %safe_app, "Quitting application");
                 let script = format!(r#"tell application "{safe_app}" to quit"#);
                 run_osascript(&script)
                     .await
                     .map_err(|e| ToolError::ExecutionFailed {
-                        name: "macos_app_control".into(),
+                        name: "app_control".into(),
                         message: e,
                     })?;
                 Ok(ToolOutput::text(format!("Quit '{app}'.")))
@@ -734,7 +776,7 @@ impl Tool for MacosAppControlTool {
                     run_osascript(script)
                         .await
                         .map_err(|e| ToolError::ExecutionFailed {
-                            name: "macos_app_control".into(),
+                            name: "app_control".into(),
                             message: e,
                         })?;
                 let apps: Vec<&str> = result.split(", ").collect();
@@ -745,20 +787,20 @@ impl Tool for MacosAppControlTool {
                 Ok(ToolOutput::text(output))
             }
             "activate" => {
-                let app = require_str(&args, "app_name", "macos_app_control")?;
+                let app = require_str(&args, "app_name", "app_control")?;
                 let safe_app = sanitize_applescript_string(app);
                 debug!(app = %safe_app, "Activating application");
                 let script = format!(r#"tell application "{safe_app}" to activate"#);
                 run_osascript(&script)
                     .await
                     .map_err(|e| ToolError::ExecutionFailed {
-                        name: "macos_app_control".into(),
+                        name: "app_control".into(),
                         message: e,
                     })?;
                 Ok(ToolOutput::text(format!("Activated '{app}'.")))
             }
             other => Err(ToolError::InvalidArguments {
-                name: "macos_app_control".to_string(),
+                name: "app_control".to_string(),
                 reason: format!(
                     "unknown action '{}'. Valid actions: open, quit, list_running, activate",
                     other
@@ -852,7 +894,119 @@ impl Tool for MacosNotificationTool {
 
 // ── 6. Clipboard Tool ───────────────────────────────────────────────────────
 
-pub struct MacosClipboardTool;
+pub struct MacosClipboardTool {
+    workspace: PathBuf,
+}
+
+impl MacosClipboardTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+
+    /// Open the clipboard history, `None` when nothing has been recorded.
+    fn history(&self) -> Result<Option<ClipboardHistory>, ToolError> {
+        if !ClipboardHistory::exists(&self.workspace) {
+            return Ok(None);
+        }
+        let config = rustant_core::config::load_config(Some(&self.workspace), None)
+            .map(|c| c.clipboard)
+            .unwrap_or_default();
+        ClipboardHistory::open(&self.workspace, &config)
+            .map(Some)
+            .map_err(|e| ToolError::ExecutionFailed {
+                name: "macos_clipboard".into(),
+                message: e.to_string(),
+            })
+    }
+
+    /// The history, search, attach and purge actions.
+    fn history_action(
+        &self,
+        action: &str,
+        args: &serde_json::Value,
+    ) -> Result<ToolOutput, ToolError> {
+        let Some(mut history) = self.history()? else {
+            return Ok(ToolOutput::text(
+                "No clipboard history. It is recorded while `rustant ui` runs with \
+                 [clipboard] history = true.",
+            ));
+        };
+        let limit = args["limit"].as_u64().unwrap_or(10) as usize;
+        let entries = match action {
+            "history" => history.recent(limit),
+            "search" => {
+                let query = require_str(args, "query", "macos_clipboard")?;
+                history.search(query, limit)
+            }
+            "attach" => {
+                let id = args["id"]
+                    .as_u64()
+                    .ok_or_else(|| ToolError::InvalidArguments {
+                        name: "macos_clipboard".into(),
+                        reason: "'id' is required for attach".into(),
+                    })?;
+                let entry = history.get(id).map_err(|e| ToolError::InvalidArguments {
+                    name: "macos_clipboard".into(),
+                    reason: e.to_string(),
+                })?;
+                let output = ToolOutput::text(entry.context_block());
+                if entry.kind != ClipKind::Image {
+                    return Ok(output);
+                }
+                let bytes = history
+                    .image_bytes(entry)
+                    .map_err(|e| ToolError::ExecutionFailed {
+                        name: "macos_clipboard".into(),
+                        message: e.to_string(),
+                    })?;
+                return Ok(output.with_attachment(Attachment::bytes(
+                    "image/png",
+                    format!("Clipboard image #{}", entry.id),
+                    bytes,
+                )));
+            }
+            _ => {
+                let count = history.purge().map_err(|e| ToolError::ExecutionFailed {
+                    name: "macos_clipboard".into(),
+                    message: e.to_string(),
+                })?;
+                return Ok(ToolOutput::text(format!(
+                    "Deleted {} clipboard history entr{}.",
+                    count,
+                    if count == 1 { "y" } else { "ies" }
+                )));
+            }
+        };
+        if entries.is_empty() {
+            return Ok(ToolOutput::text("No matching clipboard entries."));
+        }
+        let mut lines = vec![format!(
+            "Clipboard history ({} entries, newest first):",
+            entries.len()
+        )];
+        for entry in &entries {
+            lines.push(format!(
+                "#{} [{}] {} — {}",
+                entry.id,
+                entry.kind,
+                entry.copied_at.format("%Y-%m-%d %H:%M"),
+                entry.preview(80)
+            ));
+        }
+        lines.push("Use action 'attach' with an id to read an entry in full.".into());
+        let mut output = ToolOutput::text(lines.join("\n"));
+        output.metadata.insert(
+            "entries".into(),
+            json!(
+                entries
+                    .iter()
+                    .map(|e| json!({"id": e.id, "kind": e.kind, "copied_at": e.copied_at}))
+                    .collect::<Vec<_>>()
+            ),
+        );
+        Ok(output)
+    }
+}
 
 #[async_trait]
 impl Tool for MacosClipboardTool {
@@ -862,7 +1016,9 @@ impl Tool for MacosClipboardTool {
 
     fn description(&self) -> &str {
         "Read from or write to the macOS clipboard. Actions: read (get clipboard contents), \
-         write (set clipboard contents)."
+         write (set clipboard contents), read_image (attach the image on the clipboard), \
+         history (list recently copied entries), search (find entries by text), \
+         attach (read a history entry in full by id), purge (delete the history)."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -871,12 +1027,24 @@ impl Tool for MacosClipboardTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["read", "write"],
-                    "description": "Action: read or write"
+                    "enum": ["read", "write", "read_image", "history", "search", "attach", "purge"],
+                    "description": "Action: read, write, read_image, history, search, attach or purge"
                 },
                 "content": {
                     "type": "string",
                     "description": "Text to copy to clipboard (required for write)"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Text to find in clipboard history (required for search)"
+                },
+                "id": {
+                    "type": "integer",
+                    "description": "Clipboard history entry id (required for attach)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Entries to list for history and search (default 10)"
                 }
             },
             "required": ["action"]
@@ -932,9 +1100,35 @@ impl Tool for MacosClipboardTool {
                     content.len()
                 )))
             }
+            "read_image" => {
+                let path = std::env::temp_dir().join(format!(
+                    "rustant_clipboard_{}.png",
+                    chrono::Utc::now().timestamp_millis()
+                ));
+                let path_str = path.to_string_lossy().to_string();
+                debug!(path = %path_str, "Saving clipboard image");
+                let script = format!(
+                    r#"set png to the clipboard as «class PNGf»
+set f to open for access POSIX file "{}" with write permission
+set eof f to 0
+write png to f
+close access f"#,
+                    sanitize_applescript_string(&path_str)
+                );
+                if run_osascript(&script).await.is_err() {
+                    return Ok(ToolOutput::text("Clipboard does not contain an image."));
+                }
+                Ok(ToolOutput::text("Saved the clipboard image.")
+                    .with_attachment(Attachment::file("image/png", "Clipboard image", path)))
+            }
+            "history" | "search" | "attach" | "purge" => self.history_action(action, &args),
             other => Err(ToolError::InvalidArguments {
                 name: "macos_clipboard".to_string(),
-                reason: format!("unknown action '{}'. Valid actions: read, write", other),
+                reason: format!(
+                    "unknown action '{}'. Valid actions: read, write, read_image, history, \
+                     search, attach, purge",
+                    other
+                ),
             }),
         }
     }
@@ -1016,7 +1210,11 @@ impl Tool for MacosScreenshotTool {
                 message: e,
             })?;
 
-        Ok(ToolOutput::text(format!("Screenshot saved to: {path}")))
+        Ok(
+            ToolOutput::text(format!("Screenshot saved to: {path}")).with_attachment(
+                Attachment::file("image/png", format!("Screenshot ({mode})"), path),
+            ),
+        )
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -1473,7 +1671,239 @@ impl Tool for MacosFocusModeTool {
 
 // ── 12. Mail.app Tool ─────────────────────────────────────────────────────
 
-pub struct MacosMailTool;
+/// Messages listed in a bulk dry run before the rest are summarized.
+const MAIL_PLAN_DISPLAY_LIMIT: usize = 50;
+
+pub struct MacosMailTool {
+    workspace: PathBuf,
+    progress_tx: Option<mpsc::UnboundedSender<ProgressUpdate>>,
+}
+
+impl MacosMailTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self {
+            workspace,
+            progress_tx: None,
+        }
+    }
+
+    /// Create a mail tool that reports bulk-operation progress per chunk.
+    pub fn with_progress(workspace: PathBuf, tx: mpsc::UnboundedSender<ProgressUpdate>) -> Self {
+        Self {
+            workspace,
+            progress_tx: Some(tx),
+        }
+    }
+
+    fn invalid(reason: impl Into<String>) -> ToolError {
+        ToolError::InvalidArguments {
+            name: "macos_mail".to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    fn failed(message: impl std::fmt::Display) -> ToolError {
+        ToolError::ExecutionFailed {
+            name: "macos_mail".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    fn operation_arg(args: &serde_json::Value) -> Result<BulkOperation, ToolError> {
+        let op = require_str(args, "operation", "macos_mail")?;
+        BulkOperation::parse(op).ok_or_else(|| {
+            Self::invalid(format!(
+                "operation must be mark_read, flag, move or delete, not '{}'",
+                op
+            ))
+        })
+    }
+
+    /// `bulk` without a plan_id is the dry run; with one it executes the plan.
+    async fn bulk(&self, args: &serde_json::Value) -> Result<ToolOutput, ToolError> {
+        let operation = Self::operation_arg(args)?;
+        match args["plan_id"].as_str() {
+            None => self.bulk_dry_run(operation, args).await,
+            Some(id) => self.bulk_execute(operation, id, args).await,
+        }
+    }
+
+    async fn bulk_dry_run(
+        &self,
+        operation: BulkOperation,
+        args: &serde_json::Value,
+    ) -> Result<ToolOutput, ToolError> {
+        let filter = MailFilter::from_args(args).map_err(Self::invalid)?;
+        let destination = match operation {
+            BulkOperation::Move => Some(MailboxRef {
+                mailbox: Some(require_str(args, "destination", "macos_mail")?.to_string()),
+                account: args["destination_account"].as_str().map(str::to_string),
+            }),
+            _ => None,
+        };
+        debug!(filter = %filter.describe(), op = operation.as_str(), "Bulk mail dry run");
+        let listing = run_osascript(&mail_triage::list_script(&filter))
+            .await
+            .map_err(Self::failed)?;
+        let messages = mail_triage::parse_listing(&listing);
+        if messages.is_empty() {
+            return Ok(ToolOutput::text(format!(
+                "No messages match ({}).",
+                filter.describe()
+            )));
+        }
+        let plan = BulkPlan::new(operation, destination, filter, messages);
+        mail_triage::save_plan(&self.workspace, &plan).map_err(Self::failed)?;
+
+        let mut output = plan.render(MAIL_PLAN_DISPLAY_LIMIT);
+        if plan.messages.len() >= mail_triage::MAX_MATCHES {
+            output.push_str(&format!(
+                "Matches were capped at {}; narrow the filter to reach the rest.\n",
+                mail_triage::MAX_MATCHES
+            ));
+        }
+        output.push_str(&format!(
+            "\nDry run — nothing has changed. After review, call macos_mail with action \
+             \"bulk\", operation \"{}\", plan_id \"{}\" and count {}.",
+            operation.as_str(),
+            plan.id,
+            plan.messages.len()
+        ));
+        let mut result = ToolOutput::text(output);
+        result
+            .metadata
+            .insert("plan_id".into(), json!(plan.id.clone()));
+        result
+            .metadata
+            .insert("count".into(), json!(plan.messages.len()));
+        Ok(result)
+    }
+
+    async fn bulk_execute(
+        &self,
+        operation: BulkOperation,
+        id: &str,
+        args: &serde_json::Value,
+    ) -> Result<ToolOutput, ToolError> {
+        let Some(plan) = mail_triage::load_plan(&self.workspace, id) else {
+            return Ok(ToolOutput::error(format!(
+                "No bulk plan '{}'. Run the dry run again (bulk without plan_id).",
+                id
+            )));
+        };
+        if plan.is_expired(chrono::Utc::now()) {
+            mail_triage::remove_plan(&self.workspace, id);
+            return Ok(ToolOutput::error(format!(
+                "Bulk plan '{}' has expired. Run the dry run again.",
+                id
+            )));
+        }
+        // The operation and count are part of what was approved; they must
+        // describe the stored plan exactly.
+        if plan.operation != operation || args["count"].as_u64() != Some(plan.messages.len() as u64)
+        {
+            return Ok(ToolOutput::error(format!(
+                "Plan '{}' is a {} of {} message(s); pass exactly that operation and count.",
+                id,
+                plan.operation.as_str(),
+                plan.messages.len()
+            )));
+        }
+
+        let chunks = plan.chunk_scripts();
+        let total = chunks.len();
+        let mut changed = 0usize;
+        let mut error = None;
+        for (index, (ids, script)) in chunks.iter().enumerate() {
+            match run_osascript(script).await {
+                Ok(out) => changed += out.trim().parse::<usize>().unwrap_or(0),
+                Err(e) => {
+                    error = Some(format!(
+                        "chunk {}/{} (ids {}..{}) failed: {}",
+                        index + 1,
+                        total,
+                        ids[0],
+                        ids[ids.len() - 1],
+                        e
+                    ));
+                    break;
+                }
+            }
+            if let Some(tx) = &self.progress_tx {
+                let _ = tx.send(ProgressUpdate::ToolProgress {
+                    tool: "macos_mail".into(),
+                    stage: format!("{} chunk {}/{}", operation.as_str(), index + 1, total),
+                    percent: Some((index + 1) as f32 / total as f32),
+                });
+            }
+        }
+
+        let entry = mail_triage::BulkLogEntry {
+            timestamp: chrono::Utc::now(),
+            plan_id: plan.id.clone(),
+            operation,
+            destination: plan.destination.clone(),
+            filter: plan.filter.clone(),
+            message_ids: plan.ids(),
+            changed,
+            error: error.clone(),
+        };
+        if let Err(e) = mail_triage::append_log(&self.workspace, &entry) {
+            tracing::warn!(error = %e, "Failed to write mail bulk log");
+        }
+        mail_triage::remove_plan(&self.workspace, id);
+
+        let summary = format!(
+            "{}: {} of {} message(s) changed. Logged to {}.",
+            operation.as_str(),
+            changed,
+            plan.messages.len(),
+            mail_triage::log_path(std::path::Path::new("")).display()
+        );
+        Ok(match error {
+            Some(e) => ToolOutput::error(format!("{}\nStopped early — {}", summary, e)),
+            None => ToolOutput::text(summary),
+        })
+    }
+
+    /// Turn a triage decision (a bulk plan) into a Mail rule so future
+    /// messages are handled the same way.
+    async fn create_rule(&self, args: &serde_json::Value) -> Result<ToolOutput, ToolError> {
+        let id = require_str(args, "plan_id", "macos_mail")?;
+        let Some(plan) = mail_triage::load_plan(&self.workspace, id) else {
+            return Ok(ToolOutput::error(format!("No bulk plan '{}'.", id)));
+        };
+        if plan.filter.sender.is_none() && plan.filter.subject.is_none() {
+            return Ok(ToolOutput::error(
+                "A rule needs a sender or subject_pattern condition; date ranges cannot be rules."
+                    .to_string(),
+            ));
+        }
+        let name = args["name"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Rustant: {}", plan.filter.describe()));
+        let script = mail_triage::rule_script(
+            &name,
+            &plan.filter,
+            plan.operation,
+            plan.destination.as_ref(),
+        );
+        let result = run_osascript(&script).await.map_err(Self::failed)?;
+        Ok(ToolOutput::text(result))
+    }
+
+    async fn summary(&self, args: &serde_json::Value) -> Result<ToolOutput, ToolError> {
+        let days = args["days"].as_u64().unwrap_or(7).clamp(1, 365) as u32;
+        let top = args["limit"].as_u64().unwrap_or(10) as usize;
+        let output = run_osascript(&mail_triage::summary_script(days))
+            .await
+            .map_err(Self::failed)?;
+        Ok(ToolOutput::text(mail_triage::render_summary(
+            &output, days, top,
+        )))
+    }
+}
 
 #[async_trait]
 impl Tool for MacosMailTool {
@@ -1482,10 +1912,15 @@ impl Tool for MacosMailTool {
     }
 
     fn description(&self) -> &str {
-        "Read, search, and send emails via macOS Mail.app. Actions: list_unread (show unread emails), \
-         read (read a specific email by subject), search (find emails by query), \
+        "Read, search, send, and triage emails via macOS Mail.app. Actions: list_unread (show unread emails), \
+         read (read a specific email by subject), search (find emails by subject words or \
+         \"quoted phrases\", optionally filtered by sender, since/until and mailbox; page with offset), \
          compose (open compose window — does NOT auto-send), \
-         send (compose and send email — REQUIRES approval)."
+         send (compose and send email — REQUIRES approval), \
+         summary (unread counts per mailbox and top senders over the last `days`), \
+         bulk (mark_read/flag/move/delete messages matching sender, subject_pattern, since/until, mailbox — \
+         the first call is always a dry run returning plan_id and count; call again with both to execute), \
+         create_rule (make a Mail rule from a bulk plan_id)."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -1494,12 +1929,66 @@ impl Tool for MacosMailTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["list_unread", "read", "search", "compose", "send"],
+                    "enum": ["list_unread", "read", "search", "compose", "send", "summary", "bulk", "create_rule"],
                     "description": "Action to perform"
                 },
+                "operation": {
+                    "type": "string",
+                    "enum": ["mark_read", "flag", "move", "delete"],
+                    "description": "Bulk operation (for bulk)"
+                },
+                "sender": {
+                    "type": "string",
+                    "description": "Sender text to match (for search/bulk)"
+                },
+                "subject_pattern": {
+                    "type": "string",
+                    "description": "Subject text to match; a leading or trailing * matches a suffix or prefix (for bulk)"
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Earliest date received, YYYY-MM-DD (for search/bulk)"
+                },
+                "until": {
+                    "type": "string",
+                    "description": "Latest date received, YYYY-MM-DD (for search/bulk)"
+                },
+                "mailbox": {
+                    "type": "string",
+                    "description": "Mailbox to search (default: inbox)"
+                },
+                "account": {
+                    "type": "string",
+                    "description": "Account owning the mailbox"
+                },
+                "destination": {
+                    "type": "string",
+                    "description": "Target mailbox (for bulk move)"
+                },
+                "destination_account": {
+                    "type": "string",
+                    "description": "Account owning the target mailbox (for bulk move)"
+                },
+                "plan_id": {
+                    "type": "string",
+                    "description": "Dry-run plan to execute (bulk) or turn into a rule (create_rule)"
+                },
+                "count": {
+                    "type": "integer",
+                    "description": "Number of messages in the plan, as reported by the dry run (bulk execution)"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Rule name (for create_rule)"
+                },
+                "days": {
+                    "type": "integer",
+                    "description": "Window for top senders in days (for summary, default: 7)"
+                },
                 "query": {
                     "type": "string",
-                    "description": "Search query or subject to match (for read/search)"
+                    "description": "Subject to match (for read), or subject words and \"quoted phrases\" \
+                        (for search; optional when sender, since or until is given)"
                 },
                 "to": {
                     "type": "string",
@@ -1516,6 +2005,10 @@ impl Tool for MacosMailTool {
                 "limit": {
                     "type": "integer",
                     "description": "Max results to return (default: 10)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Matches to skip, for the next page (for search)"
                 }
             },
             "required": ["action"]
@@ -1585,33 +2078,34 @@ end tell"#
                 Ok(ToolOutput::text(result))
             }
             "search" => {
-                let query = sanitize_applescript_string(require_str(&args, "query", "macos_mail")?);
-                let limit = args["limit"].as_u64().unwrap_or(10);
-                debug!(query = %query, "Searching emails");
-                let script = format!(
-                    r#"tell application "Mail"
-    set output to ""
-    set counter to 0
-    set matchingMessages to (every message of inbox whose subject contains "{query}")
-    repeat with msg in matchingMessages
-        set counter to counter + 1
-        if counter > {limit} then exit repeat
-        set output to output & (subject of msg) & " | From: " & (sender of msg) & " | " & (date received of msg as string) & linefeed
-    end repeat
-    if output is "" then
-        return "No emails found matching '{query}'."
-    end if
-    return output
-end tell"#
-                );
-                let result =
-                    run_osascript(&script)
-                        .await
-                        .map_err(|e| ToolError::ExecutionFailed {
-                            name: "macos_mail".into(),
-                            message: e,
-                        })?;
-                Ok(ToolOutput::text(result))
+                let query = args["query"].as_str().unwrap_or("").trim();
+                let filter = MailFilter::from_search_args(&args).map_err(Self::invalid)?;
+                if query.is_empty() && filter.whose_clause().is_empty() {
+                    return Err(Self::invalid(
+                        "search needs a query, or one of sender, since, until",
+                    ));
+                }
+                let limit = args["limit"].as_u64().unwrap_or(10) as usize;
+                let offset = args["offset"].as_u64().unwrap_or(0) as usize;
+                debug!(query = %query, filter = %filter.describe(), offset, "Searching emails");
+                let script = mail_triage::search_script(query, &filter, offset, limit);
+                let result = run_osascript(&script).await.map_err(Self::failed)?;
+                if result.trim().is_empty() {
+                    return Ok(ToolOutput::text(format!(
+                        "No emails found matching '{}' ({}).",
+                        query,
+                        filter.describe()
+                    )));
+                }
+                let shown = result.lines().filter(|l| !l.trim().is_empty()).count();
+                let mut output = result;
+                if shown == limit {
+                    output.push_str(&format!(
+                        "\nMore may follow: search again with offset {}.",
+                        offset + shown
+                    ));
+                }
+                Ok(ToolOutput::text(output))
             }
             "compose" => {
                 let to = args["to"]
@@ -1679,10 +2173,14 @@ end tell"#
                         })?;
                 Ok(ToolOutput::text(result))
             }
+            "summary" => self.summary(&args).await,
+            "bulk" => self.bulk(&args).await,
+            "create_rule" => self.create_rule(&args).await,
             other => Err(ToolError::InvalidArguments {
                 name: "macos_mail".to_string(),
                 reason: format!(
-                    "unknown action '{}'. Valid actions: list_unread, read, search, compose, send",
+                    "unknown action '{}'. Valid actions: list_unread, read, search, compose, \
+                     send, summary, bulk, create_rule",
                     other
                 ),
             }),
@@ -2124,7 +2622,7 @@ mod tests {
 
     #[test]
     fn test_app_control_tool_name() {
-        assert_eq!(MacosAppControlTool.name(), "macos_app_control");
+        assert_eq!(MacosAppControlTool.name(), "app_control");
     }
 
     #[test]
@@ -2178,41 +2676,55 @@ mod tests {
 
     // ── Clipboard Tool Tests ────────────────────────────────────────────
 
+    fn clipboard_tool() -> MacosClipboardTool {
+        MacosClipboardTool::new(std::env::temp_dir())
+    }
+
     #[test]
     fn test_clipboard_tool_name() {
-        assert_eq!(MacosClipboardTool.name(), "macos_clipboard");
+        assert_eq!(clipboard_tool().name(), "macos_clipboard");
     }
 
     #[test]
     fn test_clipboard_risk_level() {
-        assert_eq!(MacosClipboardTool.risk_level(), RiskLevel::Write);
+        assert_eq!(clipboard_tool().risk_level(), RiskLevel::Write);
     }
 
     #[test]
     fn test_clipboard_schema_has_required_fields() {
-        let schema = MacosClipboardTool.parameters_schema();
+        let schema = clipboard_tool().parameters_schema();
         let required = schema["required"].as_array().unwrap();
         assert!(required.contains(&json!("action")));
     }
 
     #[test]
     fn test_clipboard_missing_action_returns_error() {
-        let result = rt().block_on(MacosClipboardTool.execute(json!({})));
+        let result = rt().block_on(clipboard_tool().execute(json!({})));
         assert!(matches!(result, Err(ToolError::InvalidArguments { .. })));
     }
 
     #[test]
     fn test_clipboard_invalid_action_returns_error() {
-        let result = rt().block_on(MacosClipboardTool.execute(json!({"action": "clear"})));
+        let result = rt().block_on(clipboard_tool().execute(json!({"action": "clear"})));
         assert!(matches!(result, Err(ToolError::InvalidArguments { .. })));
     }
 
     #[test]
     fn test_clipboard_write_missing_content_returns_error() {
-        let result = rt().block_on(MacosClipboardTool.execute(json!({"action": "write"})));
+        let result = rt().block_on(clipboard_tool().execute(json!({"action": "write"})));
         assert!(matches!(result, Err(ToolError::InvalidArguments { .. })));
     }
 
+    #[test]
+    fn test_clipboard_history_without_recording_explains_how_to_enable() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tool = MacosClipboardTool::new(dir.path().to_path_buf());
+        let output = rt()
+            .block_on(tool.execute(json!({"action": "history"})))
+            .unwrap();
+        assert!(output.content.contains("[clipboard] history = true"));
+    }
+
     // ── Screenshot Tool Tests ───────────────────────────────────────────
 
     #[test]
@@ -2388,19 +2900,23 @@ mod tests {
 
     // ── Mail Tool Tests ─────────────────────────────────────────────────
 
+    fn mail_tool() -> MacosMailTool {
+        MacosMailTool::new(std::env::temp_dir())
+    }
+
     #[test]
     fn test_mail_tool_name() {
-        assert_eq!(MacosMailTool.name(), "macos_mail");
+        assert_eq!(mail_tool().name(), "macos_mail");
     }
 
     #[test]
     fn test_mail_risk_level() {
-        assert_eq!(MacosMailTool.risk_level(), RiskLevel::Write);
+        assert_eq!(mail_tool().risk_level(), RiskLevel::Write);
     }
 
     #[test]
     fn test_mail_schema_has_required_fields() {
-        let schema = MacosMailTool.parameters_schema();
+        let schema = mail_tool().parameters_schema();
         let required = schema["required"].as_array().unwrap();
         assert!(required.contains(&json!("action")));
         let actions = schema["properties"]["action"]["enum"].as_array().unwrap();
@@ -2408,17 +2924,48 @@ mod tests {
         assert!(actions.contains(&json!("read")));
         assert!(actions.contains(&json!("search")));
         assert!(actions.contains(&json!("compose")));
+        assert!(actions.contains(&json!("bulk")));
+        assert!(actions.contains(&json!("summary")));
+    }
+
+    #[test]
+    fn test_mail_bulk_execute_requires_matching_plan() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tool = MacosMailTool::new(dir.path().to_path_buf());
+        let filter = MailFilter::from_args(&json!({"sender": "bot@example.com"})).unwrap();
+        let messages = mail_triage::parse_listing("7\tbot@example.com\tPing\tToday\n");
+        let plan = BulkPlan::new(BulkOperation::Delete, None, filter, messages);
+        mail_triage::save_plan(dir.path(), &plan).unwrap();
+
+        let mismatch = rt()
+            .block_on(tool.execute(json!({
+                "action": "bulk", "operation": "delete", "plan_id": plan.id, "count": 5
+            })))
+            .unwrap();
+        assert!(
+            mismatch
+                .content
+                .contains("pass exactly that operation and count")
+        );
+        assert!(mail_triage::load_plan(dir.path(), &plan.id).is_some());
+
+        let missing = rt()
+            .block_on(tool.execute(json!({
+                "action": "bulk", "operation": "delete", "plan_id": "nope", "count": 1
+            })))
+            .unwrap();
+        assert!(missing.content.contains("No bulk plan"));
     }
 
     #[test]
     fn test_mail_missing_action_returns_error() {
-        let result = rt().block_on(MacosMailTool.execute(json!({})));
+        let result = rt().block_on(mail_tool().execute(json!({})));
         assert!(matches!(result, Err(ToolError::InvalidArguments { .. })));
     }
 
     #[test]
     fn test_mail_invalid_action_returns_error() {
-        let result = rt().block_on(MacosMailTool.execute(json!({"action": "delete"})));
+        let result = rt().block_on(mail_tool().execute(json!({"action": "delete"})));
         assert!(matches!(result, Err(ToolError::InvalidArguments { .. })));
     }
 
@@ -0,0 +1,744 @@
+//! Mail triage — filters, dry-run plans, and AppleScript builders for bulk
+//! operations on Mail.app messages.
+//!
+//! A bulk operation always runs in two passes. The dry run resolves a
+//! [`MailFilter`] to concrete message ids and stores them as a
+//! [`BulkPlan`] under `.rustant/mail/plans/`; the execution pass acts on
+//! exactly those ids, in chunks, and appends a [`BulkLogEntry`] to
+//! `.rustant/mail/bulk_log.jsonl`. Nothing here talks to Mail.app directly,
+//! so the logic is testable off macOS; `macos::MacosMailTool` runs the
+//! generated scripts.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Workspace-relative directory holding triage state.
+const MAIL_DIR: &str = ".rustant/mail";
+/// Messages acted on per AppleScript invocation.
+pub const CHUNK_SIZE: usize = 50;
+/// Upper bound on messages a single dry run may match.
+pub const MAX_MATCHES: usize = 2000;
+/// Plans older than this are refused; run the dry run again.
+const PLAN_TTL_HOURS: i64 = 24;
+
+/// Escape a string for inclusion in an AppleScript string literal.
+fn quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(['\n', '\r', '\t'], " ")
+        .replace('\0', "");
+    format!("\"{}\"", escaped)
+}
+
+/// A mailbox, optionally scoped to an account. `None` everywhere means the
+/// unified inbox.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MailboxRef {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mailbox: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+impl MailboxRef {
+    fn new(mailbox: Option<&str>, account: Option<&str>) -> Self {
+        Self {
+            mailbox: mailbox.map(str::to_string),
+            account: account.map(str::to_string),
+        }
+    }
+
+    /// AppleScript expression for this mailbox.
+    pub fn expr(&self) -> String {
+        match (&self.mailbox, &self.account) {
+            (None, None) => "inbox".to_string(),
+            (Some(m), None) if m.eq_ignore_ascii_case("inbox") => "inbox".to_string(),
+            (None, Some(a)) => format!("mailbox \"INBOX\" of account {}", quote(a)),
+            (Some(m), Some(a)) => format!("mailbox {} of account {}", quote(m), quote(a)),
+            (Some(m), None) => format!("mailbox {}", quote(m)),
+        }
+    }
+
+    fn label(&self) -> String {
+        match (&self.mailbox, &self.account) {
+            (None, None) => "Inbox".to_string(),
+            (m, Some(a)) => format!("{}/{}", a, m.as_deref().unwrap_or("INBOX")),
+            (Some(m), None) => m.clone(),
+        }
+    }
+}
+
+/// Which messages a bulk operation or rule applies to. Text criteria match
+/// case-insensitively; a subject pattern may start or end with `*` to match
+/// a prefix or suffix instead of a substring.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MailFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<NaiveDate>,
+    #[serde(default)]
+    pub source: MailboxRef,
+}
+
+impl MailFilter {
+    /// Read a filter from tool arguments. At least one of sender, subject,
+    /// since, or until is required so a bulk operation never matches a whole
+    /// mailbox by accident.
+    pub fn from_args(args: &Value) -> Result<Self, String> {
+        let filter = Self::from_search_args(args)?;
+        if filter.sender.is_none()
+            && filter.subject.is_none()
+            && filter.since.is_none()
+            && filter.until.is_none()
+        {
+            return Err(
+                "a bulk filter needs at least one of sender, subject_pattern, since, until"
+                    .to_string(),
+            );
+        }
+        Ok(filter)
+    }
+
+    /// Read a filter from tool arguments, allowing one with no criteria.
+    pub fn from_search_args(args: &Value) -> Result<Self, String> {
+        let text = |key: &str| {
+            args[key]
+                .as_str()
+                .map(str::trim)
+                .filter(|s| !s.trim_matches('*').is_empty())
+                .map(str::to_string)
+        };
+        let date = |key: &str| -> Result<Option<NaiveDate>, String> {
+            args[key]
+                .as_str()
+                .map(|s| {
+                    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                        .map_err(|_| format!("'{}' must be a YYYY-MM-DD date, not '{}'", key, s))
+                })
+                .transpose()
+        };
+        let filter = Self {
+            sender: text("sender"),
+            subject: text("subject_pattern"),
+            since: date("since")?,
+            until: date("until")?,
+            source: MailboxRef::new(args["mailbox"].as_str(), args["account"].as_str()),
+        };
+        if let (Some(since), Some(until)) = (filter.since, filter.until)
+            && since > until
+        {
+            return Err(format!("since ({}) is after until ({})", since, until));
+        }
+        Ok(filter)
+    }
+
+    /// `(qualifier, text)` for the subject pattern, as Mail names them.
+    fn subject_match(&self) -> Option<(&'static str, &str)> {
+        let pattern = self.subject.as_deref()?;
+        Some(
+            match (pattern.strip_prefix('*'), pattern.strip_suffix('*')) {
+                (Some(rest), Some(_)) => ("contains", rest.trim_end_matches('*')),
+                (None, Some(prefix)) => ("begins with", prefix),
+                (Some(suffix), None) => ("ends with", suffix),
+                (None, None) => ("contains", pattern),
+            },
+        )
+    }
+
+    /// AppleScript statements defining `sinceDate` / `untilDate`, built
+    /// field by field so they do not depend on the system date format.
+    fn date_prelude(&self) -> String {
+        let define = |name: &str, d: NaiveDate, end_of_day: bool| {
+            use chrono::Datelike;
+            format!(
+                "    set {name} to current date\n    set day of {name} to 1\n    \
+                 set year of {name} to {}\n    set month of {name} to {}\n    \
+                 set day of {name} to {}\n    set time of {name} to {}\n",
+                d.year(),
+                d.month(),
+                d.day(),
+                if end_of_day { 86399 } else { 0 }
+            )
+        };
+        let mut out = String::new();
+        if let Some(d) = self.since {
+            out.push_str(&define("sinceDate", d, false));
+        }
+        if let Some(d) = self.until {
+            out.push_str(&define("untilDate", d, true));
+        }
+        out
+    }
+
+    /// The `whose` clause selecting matching messages.
+    pub fn whose_clause(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(sender) = &self.sender {
+            parts.push(format!("sender contains {}", quote(sender)));
+        }
+        if let Some((qualifier, text)) = self.subject_match() {
+            parts.push(format!("subject {} {}", qualifier, quote(text)));
+        }
+        if self.since.is_some() {
+            parts.push("date received ≥ sinceDate".to_string());
+        }
+        if self.until.is_some() {
+            parts.push("date received ≤ untilDate".to_string());
+        }
+        parts.join(" and ")
+    }
+
+    /// One-line human description.
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!("in {}", self.source.label())];
+        if let Some(s) = &self.sender {
+            parts.push(format!("from '{}'", s));
+        }
+        if let Some(s) = &self.subject {
+            parts.push(format!("subject '{}'", s));
+        }
+        match (self.since, self.until) {
+            (Some(a), Some(b)) => parts.push(format!("{} to {}", a, b)),
+            (Some(a), None) => parts.push(format!("since {}", a)),
+            (None, Some(b)) => parts.push(format!("until {}", b)),
+            (None, None) => {}
+        }
+        parts.join(", ")
+    }
+}
+
+/// A bulk operation on matched messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkOperation {
+    MarkRead,
+    Flag,
+    Move,
+    Delete,
+}
+
+impl BulkOperation {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "mark_read" => Some(Self::MarkRead),
+            "flag" => Some(Self::Flag),
+            "move" => Some(Self::Move),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MarkRead => "mark_read",
+            Self::Flag => "flag",
+            Self::Move => "move",
+            Self::Delete => "delete",
+        }
+    }
+
+    /// The statement applied to `msg`. Mail's `delete` moves to Trash.
+    fn statement(&self, destination: Option<&MailboxRef>) -> String {
+        match self {
+            Self::MarkRead => "set read status of msg to true".to_string(),
+            Self::Flag => "set flagged status of msg to true".to_string(),
+            Self::Move => format!(
+                "move msg to {}",
+                destination.map(MailboxRef::expr).unwrap_or_default()
+            ),
+            Self::Delete => "delete msg".to_string(),
+        }
+    }
+}
+
+/// A message matched by a dry run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchedMessage {
+    pub id: i64,
+    pub sender: String,
+    pub subject: String,
+    pub date: String,
+}
+
+/// Script listing matches as `id<TAB>sender<TAB>subject<TAB>date` lines.
+pub fn list_script(filter: &MailFilter) -> String {
+    format!(
+        r#"tell application "Mail"
+{prelude}    set output to ""
+    set counter to 0
+    repeat with msg in (every message of {source} whose {clause})
+        set counter to counter + 1
+        if counter > {max} then exit repeat
+        set output to output & (id of msg) & tab & (sender of msg) & tab & (subject of msg) & tab & (date received of msg as string) & linefeed
+    end repeat
+    return output
+end tell"#,
+        prelude = filter.date_prelude(),
+        source = filter.source.expr(),
+        clause = filter.whose_clause(),
+        max = MAX_MATCHES,
+    )
+}
+
+/// Script listing one page of messages matching `query` and `filter` as
+/// `subject | From: sender | date` lines.
+///
+/// Quoted phrases in `query` must each appear in the subject, as must the
+/// remaining words in order. Messages are walked in mailbox order, so
+/// successive offsets page through one stable sequence.
+pub fn search_script(query: &str, filter: &MailFilter, offset: usize, limit: usize) -> String {
+    let parsed = rustant_core::search::parse_query(query);
+    let mut clauses: Vec<String> = parsed
+        .phrases
+        .iter()
+        .map(|p| format!("subject contains {}", quote(p)))
+        .collect();
+    if !parsed.terms.is_empty() {
+        clauses.insert(
+            0,
+            format!("subject contains {}", quote(&parsed.terms.join(" "))),
+        );
+    }
+    let filter_clause = filter.whose_clause();
+    if !filter_clause.is_empty() {
+        clauses.push(filter_clause);
+    }
+    let selection = if clauses.is_empty() {
+        format!("every message of {}", filter.source.expr())
+    } else {
+        format!(
+            "every message of {} whose {}",
+            filter.source.expr(),
+            clauses.join(" and ")
+        )
+    };
+    format!(
+        r#"tell application "Mail"
+{prelude}    set output to ""
+    set counter to 0
+    repeat with msg in ({selection})
+        set counter to counter + 1
+        if counter > {end} then exit repeat
+        if counter > {offset} then
+            set output to output & (subject of msg) & " | From: " & (sender of msg) & " | " & (date received of msg as string) & linefeed
+        end if
+    end repeat
+    return output
+end tell"#,
+        prelude = filter.date_prelude(),
+        end = offset + limit,
+    )
+}
+
+/// Parse the output of [`list_script`]. Malformed lines are skipped.
+pub fn parse_listing(output: &str) -> Vec<MatchedMessage> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            let id = fields.next()?.trim().parse().ok()?;
+            Some(MatchedMessage {
+                id,
+                sender: fields.next().unwrap_or("").trim().to_string(),
+                subject: fields.next().unwrap_or("").trim().to_string(),
+                date: fields.next().unwrap_or("").trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// A dry-run result waiting for an approved execution pass.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkPlan {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub operation: BulkOperation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<MailboxRef>,
+    pub filter: MailFilter,
+    pub messages: Vec<MatchedMessage>,
+}
+
+impl BulkPlan {
+    pub fn new(
+        operation: BulkOperation,
+        destination: Option<MailboxRef>,
+        filter: MailFilter,
+        messages: Vec<MatchedMessage>,
+    ) -> Self {
+        let created_at = Utc::now();
+        let uuid = uuid::Uuid::new_v4().simple().to_string();
+        Self {
+            id: format!("{}-{}", created_at.format("%Y%m%d-%H%M%S"), &uuid[..8]),
+            created_at,
+            operation,
+            destination,
+            filter,
+            messages,
+        }
+    }
+
+    pub fn ids(&self) -> Vec<i64> {
+        self.messages.iter().map(|m| m.id).collect()
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now - self.created_at > chrono::Duration::hours(PLAN_TTL_HOURS)
+    }
+
+    /// Scripts acting on the plan's messages, [`CHUNK_SIZE`] ids each. Each
+    /// returns the number of messages it changed.
+    pub fn chunk_scripts(&self) -> Vec<(Vec<i64>, String)> {
+        let statement = self.operation.statement(self.destination.as_ref());
+        self.ids()
+            .chunks(CHUNK_SIZE)
+            .map(|chunk| {
+                let ids = chunk
+                    .iter()
+                    .map(i64::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let script = format!(
+                    r#"tell application "Mail"
+    set changed to 0
+    repeat with mid in {{{ids}}}
+        set found to (every message of {source} whose id is (mid as integer))
+        if (count of found) > 0 then
+            set msg to item 1 of found
+            {statement}
+            set changed to changed + 1
+        end if
+    end repeat
+    return changed
+end tell"#,
+                    source = self.filter.source.expr(),
+                );
+                (chunk.to_vec(), script)
+            })
+            .collect()
+    }
+
+    /// Dry-run listing shown before approval.
+    pub fn render(&self, limit: usize) -> String {
+        let dest = self
+            .destination
+            .as_ref()
+            .map(|d| format!(" → {}", d.label()))
+            .unwrap_or_default();
+        let mut out = format!(
+            "Dry run: {}{} would touch {} message(s) ({})\n",
+            self.operation.as_str(),
+            dest,
+            self.messages.len(),
+            self.filter.describe()
+        );
+        for m in self.messages.iter().take(limit) {
+            out.push_str(&format!(
+                "  [{}] {} — {} ({})\n",
+                m.id, m.sender, m.subject, m.date
+            ));
+        }
+        if self.messages.len() > limit {
+            out.push_str(&format!("  ... {} more\n", self.messages.len() - limit));
+        }
+        out
+    }
+}
+
+fn plan_path(workspace: &Path, id: &str) -> PathBuf {
+    workspace
+        .join(MAIL_DIR)
+        .join("plans")
+        .join(format!("{}.json", id))
+}
+
+pub fn save_plan(workspace: &Path, plan: &BulkPlan) -> std::io::Result<()> {
+    let path = plan_path(workspace, &plan.id);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(plan)?)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Load a stored plan; `None` if the id is unknown or malformed.
+pub fn load_plan(workspace: &Path, id: &str) -> Option<BulkPlan> {
+    if id.is_empty() || id.contains(['/', '\\', '.']) {
+        return None;
+    }
+    let data = std::fs::read_to_string(plan_path(workspace, id)).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+/// Plans are single-use; remove one once it has been executed.
+pub fn remove_plan(workspace: &Path, id: &str) {
+    let _ = std::fs::remove_file(plan_path(workspace, id));
+}
+
+/// One executed bulk operation, as recorded in the audit log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkLogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub plan_id: String,
+    pub operation: BulkOperation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<MailboxRef>,
+    pub filter: MailFilter,
+    /// Every message id the plan matched.
+    pub message_ids: Vec<i64>,
+    /// Messages Mail reported as changed.
+    pub changed: usize,
+    /// Set when a chunk failed and the run stopped early.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub fn log_path(workspace: &Path) -> PathBuf {
+    workspace.join(MAIL_DIR).join("bulk_log.jsonl")
+}
+
+pub fn append_log(workspace: &Path, entry: &BulkLogEntry) -> std::io::Result<()> {
+    let path = log_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)
+}
+
+/// Script creating a Mail rule equivalent to a triage decision. Date ranges
+/// have no rule condition and are left out.
+pub fn rule_script(
+    name: &str,
+    filter: &MailFilter,
+    operation: BulkOperation,
+    destination: Option<&MailboxRef>,
+) -> String {
+    let mut conditions = String::new();
+    if let Some(sender) = &filter.sender {
+        conditions.push_str(&format!(
+            "        make new rule condition at end of rule conditions with properties {{rule type:from header, qualifier:does contain value, expression:{}}}\n",
+            quote(sender)
+        ));
+    }
+    if let Some((qualifier, text)) = filter.subject_match() {
+        let qualifier = match qualifier {
+            "begins with" => "begins with value",
+            "ends with" => "ends with value",
+            _ => "does contain value",
+        };
+        conditions.push_str(&format!(
+            "        make new rule condition at end of rule conditions with properties {{rule type:subject header, qualifier:{}, expression:{}}}\n",
+            qualifier,
+            quote(text)
+        ));
+    }
+    let action = match operation {
+        BulkOperation::MarkRead => "set mark read to true".to_string(),
+        BulkOperation::Flag => "set mark flagged to true".to_string(),
+        BulkOperation::Move => format!(
+            "set should move message to true\n        set move message to {}",
+            destination.map(MailboxRef::expr).unwrap_or_default()
+        ),
+        BulkOperation::Delete => "set delete message to true".to_string(),
+    };
+    format!(
+        r#"tell application "Mail"
+    set newRule to make new rule at end of rules with properties {{name:{name}, enabled:true, all conditions must be met:true}}
+    tell newRule
+{conditions}        {action}
+    end tell
+    return "Created rule " & {name}
+end tell"#,
+        name = quote(name),
+    )
+}
+
+/// Script reporting unread counts per mailbox (`U<TAB>mailbox<TAB>count`)
+/// and inbox senders received in the last `days` days (`S<TAB>sender`).
+pub fn summary_script(days: u32) -> String {
+    format!(
+        r#"tell application "Mail"
+    set output to ""
+    repeat with acct in accounts
+        repeat with mb in mailboxes of acct
+            set n to unread count of mb
+            if n > 0 then set output to output & "U" & tab & (name of acct) & "/" & (name of mb) & tab & n & linefeed
+        end repeat
+    end repeat
+    set cutoff to (current date) - ({days} * days)
+    repeat with msg in (every message of inbox whose date received ≥ cutoff)
+        set output to output & "S" & tab & (sender of msg) & linefeed
+    end repeat
+    return output
+end tell"#
+    )
+}
+
+/// Render the output of [`summary_script`]: mailboxes by unread count and
+/// the `top` most frequent senders.
+pub fn render_summary(output: &str, days: u32, top: usize) -> String {
+    let mut unread: Vec<(String, u64)> = Vec::new();
+    let mut senders: HashMap<String, usize> = HashMap::new();
+    for line in output.lines() {
+        let mut fields = line.split('\t');
+        match fields.next() {
+            Some("U") => {
+                if let (Some(mailbox), Some(count)) = (fields.next(), fields.next())
+                    && let Ok(count) = count.trim().parse()
+                {
+                    unread.push((mailbox.to_string(), count));
+                }
+            }
+            Some("S") => {
+                if let Some(sender) = fields.next().map(str::trim).filter(|s| !s.is_empty()) {
+                    *senders.entry(sender.to_string()).or_default() += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    unread.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let mut ranked: Vec<(String, usize)> = senders.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let total: u64 = unread.iter().map(|(_, n)| n).sum();
+    let mut out = format!("Unread: {} across {} mailbox(es)\n", total, unread.len());
+    for (mailbox, count) in &unread {
+        out.push_str(&format!("  {:>5}  {}\n", count, mailbox));
+    }
+    out.push_str(&format!("Top senders (last {} days):\n", days));
+    if ranked.is_empty() {
+        out.push_str("  (no messages)\n");
+    }
+    for (sender, count) in ranked.iter().take(top) {
+        out.push_str(&format!("  {:>5}  {}\n", count, sender));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_filter_requires_criteria_and_builds_clause() {
+        assert!(MailFilter::from_args(&json!({"mailbox": "Archive"})).is_err());
+        assert!(MailFilter::from_args(&json!({"subject_pattern": "*"})).is_err());
+        assert!(
+            MailFilter::from_args(&json!({"since": "2026-05-01", "until": "2026-04-01"})).is_err()
+        );
+
+        let filter = MailFilter::from_args(&json!({
+            "sender": "news@shop.example",
+            "subject_pattern": "Weekly \"deals\"*",
+            "since": "2026-03-01",
+            "mailbox": "Promotions",
+            "account": "iCloud"
+        }))
+        .unwrap();
+        assert_eq!(
+            filter.whose_clause(),
+            "sender contains \"news@shop.example\" and subject begins with \
+             \"Weekly \\\"deals\\\"\" and date received ≥ sinceDate"
+        );
+        assert_eq!(
+            filter.source.expr(),
+            "mailbox \"Promotions\" of account \"iCloud\""
+        );
+        let script = list_script(&filter);
+        assert!(script.contains("set year of sinceDate to 2026"));
+        assert!(script.contains("set month of sinceDate to 3"));
+        assert!(!script.contains("untilDate"));
+    }
+
+    #[test]
+    fn test_search_script_phrases_filters_and_offset() {
+        let filter = MailFilter::from_search_args(&json!({
+            "sender": "ci@example.com",
+            "until": "2026-04-30",
+            "mailbox": "Builds"
+        }))
+        .unwrap();
+        let script = search_script(r#"nightly "build failed""#, &filter, 20, 10);
+        assert!(script.contains(
+            "every message of mailbox \"Builds\" whose subject contains \"nightly\" and \
+             subject contains \"build failed\" and sender contains \"ci@example.com\" and \
+             date received ≤ untilDate"
+        ));
+        assert!(script.contains("if counter > 30 then exit repeat"));
+        assert!(script.contains("if counter > 20 then"));
+        assert!(script.contains("set month of untilDate to 4"));
+
+        // Search filters may be empty, unlike bulk filters.
+        let filter = MailFilter::from_search_args(&json!({})).unwrap();
+        let script = search_script("invoice", &filter, 0, 5);
+        assert!(script.contains("every message of inbox whose subject contains \"invoice\""));
+    }
+
+    #[test]
+    fn test_plan_chunks_and_round_trips() {
+        let listing: String = (1..=120)
+            .map(|i| format!("{}\tbot@example.com\tAlert {}\tMonday\n", i, i))
+            .chain(std::iter::once("garbage line\n".to_string()))
+            .collect();
+        let messages = parse_listing(&listing);
+        assert_eq!(messages.len(), 120);
+        assert_eq!(messages[4].subject, "Alert 5");
+
+        let filter = MailFilter::from_args(&json!({"sender": "bot@example.com"})).unwrap();
+        let plan = BulkPlan::new(BulkOperation::Delete, None, filter, messages);
+        let chunks = plan.chunk_scripts();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].0.len(), CHUNK_SIZE);
+        assert_eq!(chunks[2].0, (101..=120).collect::<Vec<_>>());
+        assert!(chunks[0].1.contains("delete msg"));
+        assert!(chunks[0].1.contains("{1, 2, 3,"));
+
+        let dir = TempDir::new().unwrap();
+        save_plan(dir.path(), &plan).unwrap();
+        let loaded = load_plan(dir.path(), &plan.id).unwrap();
+        assert_eq!(loaded.ids(), plan.ids());
+        assert!(load_plan(dir.path(), "../escape").is_none());
+        remove_plan(dir.path(), &plan.id);
+        assert!(load_plan(dir.path(), &plan.id).is_none());
+    }
+
+    #[test]
+    fn test_rule_script_and_summary() {
+        let filter = MailFilter::from_args(&json!({
+            "sender": "alerts@ci.example",
+            "subject_pattern": "*failed"
+        }))
+        .unwrap();
+        let dest = MailboxRef::new(Some("CI"), None);
+        let script = rule_script("CI noise", &filter, BulkOperation::Move, Some(&dest));
+        assert!(script.contains("rule type:from header"));
+        assert!(script.contains("qualifier:ends with value, expression:\"failed\""));
+        assert!(script.contains("set move message to mailbox \"CI\""));
+
+        let output = "U\tWork/INBOX\t12\nU\tHome/INBOX\t3\n\
+                      S\ta@example.com\nS\tb@example.com\nS\ta@example.com\n";
+        let summary = render_summary(output, 7, 1);
+        assert!(summary.starts_with("Unread: 15 across 2 mailbox(es)"));
+        assert!(summary.find("Work/INBOX").unwrap() < summary.find("Home/INBOX").unwrap());
+        assert!(summary.contains("2  a@example.com"));
+        assert!(!summary.contains("b@example.com"));
+    }
+}
@@ -0,0 +1,1026 @@
+//! Model registry tool — versioned models with model cards, stage promotion
+//! through configurable gates, rollback, and lineage back to the dataset and
+//! experiment-tracker run that produced them.
+//!
+//! State lives in `.rustant/models/registry.json`. Stage changes are also
+//! appended to `.rustant/models/history.jsonl`, which is never rewritten.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rustant_core::config::{ModelRegistryConfig, PromotionGate};
+use rustant_core::error::ToolError;
+use rustant_core::types::{RiskLevel, ToolOutput};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::registry::Tool;
+
+/// Stages in promotion order. A version enters a stage from the one before it.
+const STAGES: [&str; 3] = ["dev", "staging", "production"];
+
+// ---------------------------------------------------------------------------
+// Data models
+// ---------------------------------------------------------------------------
+
+/// Provenance and evaluation of one model version. Fixed at registration,
+/// except that checks can be recorded as they pass.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ModelCard {
+    dataset: Option<String>,
+    /// SHA-256 of the dataset file, when it was a file in the workspace.
+    dataset_hash: Option<String>,
+    commit: Option<String>,
+    hyperparameters: Value,
+    metrics: BTreeMap<String, f64>,
+    checks_passed: Vec<String>,
+    experiment_id: Option<String>,
+    author: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ModelVersion {
+    version: u32,
+    artifact: String,
+    card: ModelCard,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RegisteredModel {
+    name: String,
+    versions: Vec<ModelVersion>,
+    /// Versions each stage has pointed at, oldest first; the last is current.
+    /// Rollback pops the current version.
+    stages: BTreeMap<String, Vec<u32>>,
+    next_version: u32,
+}
+
+impl RegisteredModel {
+    fn version(&self, version: u32) -> Option<&ModelVersion> {
+        self.versions.iter().find(|v| v.version == version)
+    }
+
+    fn stage_version(&self, stage: &str) -> Option<u32> {
+        self.stages.get(stage).and_then(|s| s.last().copied())
+    }
+
+    /// Stages currently pointing at `version`.
+    fn stages_of(&self, version: u32) -> Vec<&str> {
+        STAGES
+            .into_iter()
+            .filter(|s| self.stage_version(s) == Some(version))
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RegistryState {
+    models: Vec<RegisteredModel>,
+}
+
+impl RegistryState {
+    fn model(&self, name: &str) -> Option<&RegisteredModel> {
+        self.models.iter().find(|m| m.name == name)
+    }
+
+    fn model_mut(&mut self, name: &str) -> Option<&mut RegisteredModel> {
+        self.models.iter_mut().find(|m| m.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum StageChange {
+    Promote,
+    Rollback,
+}
+
+/// One line of the append-only stage history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StageEvent {
+    model: String,
+    stage: String,
+    change: StageChange,
+    from: Option<u32>,
+    to: Option<u32>,
+    reason: Option<String>,
+    at: DateTime<Utc>,
+}
+
+// ---------------------------------------------------------------------------
+// Tool struct
+// ---------------------------------------------------------------------------
+
+pub struct ModelRegistryTool {
+    workspace: PathBuf,
+}
+
+impl ModelRegistryTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+
+    fn dir(&self) -> PathBuf {
+        self.workspace.join(".rustant").join("models")
+    }
+
+    fn state_path(&self) -> PathBuf {
+        self.dir().join("registry.json")
+    }
+
+    fn history_path(&self) -> PathBuf {
+        self.dir().join("history.jsonl")
+    }
+
+    fn load_state(&self) -> RegistryState {
+        std::fs::read_to_string(self.state_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_state(&self, state: &RegistryState) -> Result<(), ToolError> {
+        let path = self.state_path();
+        std::fs::create_dir_all(self.dir()).map_err(|e| failed("create registry dir", e))?;
+        let json =
+            serde_json::to_string_pretty(state).map_err(|e| failed("serialize registry", e))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, &json).map_err(|e| failed("write registry", e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| failed("rename registry file", e))?;
+        Ok(())
+    }
+
+    fn append_history(&self, event: &StageEvent) -> Result<(), ToolError> {
+        std::fs::create_dir_all(self.dir()).map_err(|e| failed("create registry dir", e))?;
+        let line = serde_json::to_string(event).map_err(|e| failed("serialize history", e))?;
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.history_path())
+            .and_then(|mut file| writeln!(file, "{}", line))
+            .map_err(|e| failed("append history", e))
+    }
+
+    fn load_history(&self) -> Vec<StageEvent> {
+        std::fs::read_to_string(self.history_path())
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect()
+    }
+
+    fn gates(&self) -> ModelRegistryConfig {
+        rustant_core::config::load_config(Some(&self.workspace), None)
+            .ok()
+            .and_then(|c| c.model_registry)
+            .unwrap_or_default()
+    }
+
+    // --- action helpers ---
+
+    fn action_register(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let name = str_arg(args, "name");
+        let artifact = str_arg(args, "artifact");
+        if name.is_empty() || artifact.is_empty() {
+            return Ok(ToolOutput::text(
+                "Please provide the model 'name' and the 'artifact' path or URI.",
+            ));
+        }
+        let dataset = opt_arg(args, "dataset");
+        let dataset_hash = opt_arg(args, "dataset_hash").or_else(|| {
+            dataset
+                .as_deref()
+                .and_then(|d| hash_file(&self.workspace.join(d)))
+        });
+        let metrics = args
+            .get("metrics")
+            .and_then(|v| v.as_object())
+            .map(|m| {
+                m.iter()
+                    .filter_map(|(k, v)| v.as_f64().map(|n| (k.clone(), n)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let card = ModelCard {
+            dataset,
+            dataset_hash,
+            commit: opt_arg(args, "commit")
+                .or_else(|| git(&self.workspace, &["rev-parse", "HEAD"])),
+            hyperparameters: args
+                .get("hyperparameters")
+                .cloned()
+                .unwrap_or_else(|| json!({})),
+            metrics,
+            checks_passed: string_list(args, "checks_passed"),
+            experiment_id: opt_arg(args, "experiment_id"),
+            author: opt_arg(args, "author")
+                .or_else(|| git(&self.workspace, &["config", "user.name"]))
+                .or_else(|| std::env::var("USER").ok()),
+            created_at: Utc::now(),
+        };
+
+        let mut state = self.load_state();
+        if state.model(name).is_none() {
+            state.models.push(RegisteredModel {
+                name: name.to_string(),
+                versions: Vec::new(),
+                stages: BTreeMap::new(),
+                next_version: 1,
+            });
+        }
+        let Some(model) = state.model_mut(name) else {
+            return Ok(ToolOutput::text(format!("Model '{}' not found.", name)));
+        };
+        let version = model.next_version;
+        model.next_version += 1;
+        model.versions.push(ModelVersion {
+            version,
+            artifact: artifact.to_string(),
+            card,
+        });
+        self.save_state(&state)?;
+        Ok(ToolOutput::text(format!(
+            "Registered {} v{} ({}). Promote it with stage 'dev' to start.",
+            name, version, artifact
+        )))
+    }
+
+    fn action_card(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let state = self.load_state();
+        let (model, version) = match resolve(&state, args) {
+            Ok(found) => found,
+            Err(message) => return Ok(ToolOutput::text(message)),
+        };
+        Ok(ToolOutput::text(format_card(model, version)))
+    }
+
+    fn action_record_check(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let check = str_arg(args, "check");
+        if check.is_empty() {
+            return Ok(ToolOutput::text(
+                "Please provide the 'check' that passed (e.g. transparency, safety).",
+            ));
+        }
+        let mut state = self.load_state();
+        let (name, version) = match resolve(&state, args) {
+            Ok((model, version)) => (model.name.clone(), version.version),
+            Err(message) => return Ok(ToolOutput::text(message)),
+        };
+        let Some(entry) = state
+            .model_mut(&name)
+            .and_then(|m| m.versions.iter_mut().find(|v| v.version == version))
+        else {
+            return Ok(ToolOutput::text(format!(
+                "{} v{} not found.",
+                name, version
+            )));
+        };
+        if !entry.card.checks_passed.iter().any(|c| c == check) {
+            entry.card.checks_passed.push(check.to_string());
+        }
+        self.save_state(&state)?;
+        Ok(ToolOutput::text(format!(
+            "Recorded '{}' as passed for {} v{}.",
+            check, name, version
+        )))
+    }
+
+    fn action_promote(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let stage = str_arg(args, "stage");
+        let Some(index) = STAGES.iter().position(|s| *s == stage) else {
+            return Ok(ToolOutput::text(format!(
+                "Please provide a 'stage': {}.",
+                STAGES.join(", ")
+            )));
+        };
+        let mut state = self.load_state();
+        let (name, version) = match resolve(&state, args) {
+            Ok((model, version)) => (model.name.clone(), version.version),
+            Err(message) => return Ok(ToolOutput::text(message)),
+        };
+        let Some(model) = state.model_mut(&name) else {
+            return Ok(ToolOutput::text(format!("Model '{}' not found.", name)));
+        };
+        let current = model.stage_version(stage);
+        if current == Some(version) {
+            return Ok(ToolOutput::text(format!(
+                "{} v{} is already in {}.",
+                name, version, stage
+            )));
+        }
+        if index > 0 && model.stage_version(STAGES[index - 1]) != Some(version) {
+            return Ok(ToolOutput::text(format!(
+                "Promotion refused: {} v{} must be in {} before {}.",
+                name,
+                version,
+                STAGES[index - 1],
+                stage
+            )));
+        }
+        let Some(card) = model.version(version).map(|v| &v.card) else {
+            return Ok(ToolOutput::text(format!(
+                "{} v{} not found.",
+                name, version
+            )));
+        };
+        let failures = self
+            .gates()
+            .gates
+            .get(stage)
+            .map(|gate| gate_failures(gate, card))
+            .unwrap_or_default();
+        if !failures.is_empty() {
+            return Ok(ToolOutput::text(format!(
+                "Promotion of {} v{} to {} refused by its gate:\n{}",
+                name,
+                version,
+                stage,
+                failures
+                    .iter()
+                    .map(|f| format!("  - {}", f))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )));
+        }
+
+        model
+            .stages
+            .entry(stage.to_string())
+            .or_default()
+            .push(version);
+        self.append_history(&StageEvent {
+            model: name.clone(),
+            stage: stage.to_string(),
+            change: StageChange::Promote,
+            from: current,
+            to: Some(version),
+            reason: opt_arg(args, "reason"),
+            at: Utc::now(),
+        })?;
+        self.save_state(&state)?;
+        Ok(ToolOutput::text(match current {
+            Some(previous) => format!(
+                "Promoted {} v{} to {} (was v{}).",
+                name, version, stage, previous
+            ),
+            None => format!("Promoted {} v{} to {}.", name, version, stage),
+        }))
+    }
+
+    fn action_rollback(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let name = str_arg(args, "name");
+        let stage = str_arg(args, "stage");
+        if !STAGES.contains(&stage) {
+            return Ok(ToolOutput::text(format!(
+                "Please provide a 'stage' to roll back: {}.",
+                STAGES.join(", ")
+            )));
+        }
+        let mut state = self.load_state();
+        let Some(model) = state.model_mut(name) else {
+            return Ok(ToolOutput::text(format!("Model '{}' not found.", name)));
+        };
+        let existing: Vec<u32> = model.versions.iter().map(|v| v.version).collect();
+        let Some(history) = model.stages.get_mut(stage).filter(|h| !h.is_empty()) else {
+            return Ok(ToolOutput::text(format!(
+                "{} has no version in {}.",
+                name, stage
+            )));
+        };
+        // Deleted versions can't be pointed at again, so skip past them.
+        let Some(previous) = history[..history.len() - 1]
+            .iter()
+            .rev()
+            .copied()
+            .find(|v| existing.contains(v))
+        else {
+            return Ok(ToolOutput::text(format!(
+                "{} has no earlier version in {} to roll back to.",
+                name, stage
+            )));
+        };
+        let current = history.pop();
+        while history.last().is_some_and(|v| *v != previous) {
+            history.pop();
+        }
+        self.append_history(&StageEvent {
+            model: name.to_string(),
+            stage: stage.to_string(),
+            change: StageChange::Rollback,
+            from: current,
+            to: Some(previous),
+            reason: opt_arg(args, "reason"),
+            at: Utc::now(),
+        })?;
+        self.save_state(&state)?;
+        Ok(ToolOutput::text(format!(
+            "Rolled {} {} back to v{}{}.",
+            name,
+            stage,
+            previous,
+            current
+                .map(|v| format!(" (from v{})", v))
+                .unwrap_or_default()
+        )))
+    }
+
+    fn action_resolve(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let state = self.load_state();
+        match resolve(&state, args) {
+            Ok((model, version)) => Ok(ToolOutput::text(format!(
+                "{} v{}: {}",
+                model.name, version.version, version.artifact
+            ))),
+            Err(message) => Ok(ToolOutput::text(message)),
+        }
+    }
+
+    fn action_lineage(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let state = self.load_state();
+        let (model, version) = match resolve(&state, args) {
+            Ok(found) => found,
+            Err(message) => return Ok(ToolOutput::text(message)),
+        };
+        let card = &version.card;
+        let mut out = format!("Lineage of {} v{}\n", model.name, version.version);
+        let stages = model.stages_of(version.version);
+        if !stages.is_empty() {
+            out.push_str(&format!("  Stages: {}\n", stages.join(", ")));
+        }
+        out.push_str(&format!("  Artifact: {}\n", version.artifact));
+
+        match &card.dataset {
+            Some(dataset) => {
+                out.push_str(&format!("  Dataset: {}\n", dataset));
+                if let Some(hash) = &card.dataset_hash {
+                    let status = match hash_file(&self.workspace.join(dataset)) {
+                        Some(now) if &now == hash => "unchanged since registration",
+                        Some(_) => "CHANGED since registration",
+                        None => "not found in the workspace",
+                    };
+                    out.push_str(&format!("    sha256 {} ({})\n", short(hash), status));
+                }
+            }
+            None => out.push_str("  Dataset: not recorded\n"),
+        }
+        out.push_str(&format!(
+            "  Commit: {}\n",
+            card.commit.as_deref().unwrap_or("not recorded")
+        ));
+
+        match &card.experiment_id {
+            Some(id) => match self.find_experiment(id) {
+                Some(run) => {
+                    out.push_str(&format!(
+                        "  Experiment run: {} — {} [{}]\n",
+                        id,
+                        run["name"].as_str().unwrap_or(""),
+                        run["status"].as_str().unwrap_or("")
+                    ));
+                    if let Some(metrics) = run["metrics"].as_object().filter(|m| !m.is_empty()) {
+                        out.push_str(&format!(
+                            "    metrics: {}\n",
+                            Value::Object(metrics.clone())
+                        ));
+                    }
+                    if let Some(hypothesis) = run["hypothesis_id"].as_str() {
+                        out.push_str(&format!("    hypothesis: {}\n", hypothesis));
+                    }
+                }
+                None => out.push_str(&format!(
+                    "  Experiment run: {} (not found in the experiment tracker)\n",
+                    id
+                )),
+            },
+            None => out.push_str("  Experiment run: not recorded\n"),
+        }
+        Ok(ToolOutput::text(out.trim_end()))
+    }
+
+    /// An experiment from the experiment tracker's state, by ID.
+    fn find_experiment(&self, id: &str) -> Option<Value> {
+        let path = self
+            .workspace
+            .join(".rustant")
+            .join("experiments")
+            .join("tracker.json");
+        let state: Value = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+        state["experiments"]
+            .as_array()?
+            .iter()
+            .find(|e| e["id"].as_str() == Some(id))
+            .cloned()
+    }
+
+    fn action_history(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let name = opt_arg(args, "name");
+        let events: Vec<StageEvent> = self
+            .load_history()
+            .into_iter()
+            .filter(|e| name.as_deref().is_none_or(|n| e.model == n))
+            .collect();
+        if events.is_empty() {
+            return Ok(ToolOutput::text("No stage changes recorded."));
+        }
+        let version = |v: Option<u32>| {
+            v.map(|v| format!("v{}", v))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let lines: Vec<String> = events
+            .iter()
+            .map(|e| {
+                format!(
+                    "{} {} {} {}: {} → {}{}",
+                    e.at.format("%Y-%m-%d %H:%M"),
+                    e.model,
+                    match e.change {
+                        StageChange::Promote => "promote",
+                        StageChange::Rollback => "rollback",
+                    },
+                    e.stage,
+                    version(e.from),
+                    version(e.to),
+                    e.reason
+                        .as_deref()
+                        .map(|r| format!(" ({})", r))
+                        .unwrap_or_default()
+                )
+            })
+            .collect();
+        Ok(ToolOutput::text(lines.join("\n")))
+    }
+
+    fn action_list(&self) -> Result<ToolOutput, ToolError> {
+        let state = self.load_state();
+        if state.models.is_empty() {
+            return Ok(ToolOutput::text("No models registered."));
+        }
+        let mut out = String::new();
+        for model in &state.models {
+            let stages: Vec<String> = STAGES
+                .iter()
+                .filter_map(|s| model.stage_version(s).map(|v| format!("{}=v{}", s, v)))
+                .collect();
+            out.push_str(&format!(
+                "{} — {} version(s){}\n",
+                model.name,
+                model.versions.len(),
+                if stages.is_empty() {
+                    String::new()
+                } else {
+                    format!(", {}", stages.join(" "))
+                }
+            ));
+        }
+        Ok(ToolOutput::text(out.trim_end()))
+    }
+
+    fn action_delete(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let mut state = self.load_state();
+        let (name, version) = match resolve(&state, args) {
+            Ok((model, version)) => (model.name.clone(), version.version),
+            Err(message) => return Ok(ToolOutput::text(message)),
+        };
+        let Some(model) = state.model_mut(&name) else {
+            return Ok(ToolOutput::text(format!("Model '{}' not found.", name)));
+        };
+        let stages = model.stages_of(version);
+        if !stages.is_empty() {
+            return Ok(ToolOutput::text(format!(
+                "Refusing to delete {} v{}: {} point(s) at it. Promote or roll back another version first.",
+                name,
+                version,
+                stages.join(", ")
+            )));
+        }
+        model.versions.retain(|v| v.version != version);
+        self.save_state(&state)?;
+        Ok(ToolOutput::text(format!(
+            "Deleted {} v{} from the registry. The artifact itself was not removed.",
+            name, version
+        )))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn failed(what: &str, e: impl std::fmt::Display) -> ToolError {
+    ToolError::ExecutionFailed {
+        name: "model_registry".to_string(),
+        message: format!("Failed to {}: {}", what, e),
+    }
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> &'a str {
+    args.get(key).and_then(|v| v.as_str()).unwrap_or("").trim()
+}
+
+fn opt_arg(args: &Value, key: &str) -> Option<String> {
+    Some(str_arg(args, key))
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn string_list(args: &Value, key: &str) -> Vec<String> {
+    args.get(key)
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The model and version an action refers to. `name` may carry a stage alias
+/// (`churn@production`); otherwise `version` is a number or a stage name,
+/// and defaults to the latest version.
+fn resolve<'a>(
+    state: &'a RegistryState,
+    args: &Value,
+) -> Result<(&'a RegisteredModel, &'a ModelVersion), String> {
+    let raw = str_arg(args, "name");
+    let (name, alias) = match raw.split_once('@') {
+        Some((name, alias)) => (name, Some(alias.to_string())),
+        None => (raw, None),
+    };
+    if name.is_empty() {
+        return Err("Please provide the model 'name'.".to_string());
+    }
+    let Some(model) = state.model(name) else {
+        return Err(format!("Model '{}' not found.", name));
+    };
+    let spec = alias.or_else(|| match args.get("version") {
+        Some(Value::Number(n)) => Some(n.to_string()),
+        Some(Value::String(s)) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        _ => None,
+    });
+    let version = match spec.as_deref() {
+        None => model.versions.last().map(|v| v.version),
+        Some(spec) if STAGES.contains(&spec) => match model.stage_version(spec) {
+            Some(v) => Some(v),
+            None => return Err(format!("{} has no version in {}.", name, spec)),
+        },
+        Some(spec) => match spec.trim_start_matches('v').parse::<u32>() {
+            Ok(v) => Some(v),
+            Err(_) => {
+                return Err(format!(
+                    "Unknown version '{}'. Use a number or a stage: {}.",
+                    spec,
+                    STAGES.join(", ")
+                ));
+            }
+        },
+    };
+    version
+        .and_then(|v| model.version(v))
+        .map(|v| (model, v))
+        .ok_or_else(|| format!("{} has no such version.", name))
+}
+
+/// Why `card` does not meet `gate`; empty when it does.
+fn gate_failures(gate: &PromotionGate, card: &ModelCard) -> Vec<String> {
+    let mut failures = Vec::new();
+    let mut thresholds: Vec<(&String, &f64, bool)> = gate
+        .min_metrics
+        .iter()
+        .map(|(k, v)| (k, v, true))
+        .chain(gate.max_metrics.iter().map(|(k, v)| (k, v, false)))
+        .collect();
+    thresholds.sort_by(|a, b| a.0.cmp(b.0));
+    for (metric, limit, minimum) in thresholds {
+        match card.metrics.get(metric) {
+            None => failures.push(format!("metric '{}' is not on the model card", metric)),
+            Some(value) if minimum && value < limit => failures.push(format!(
+                "{} = {} is below the minimum {}",
+                metric, value, limit
+            )),
+            Some(value) if !minimum && value > limit => failures.push(format!(
+                "{} = {} is above the maximum {}",
+                metric, value, limit
+            )),
+            Some(_) => {}
+        }
+    }
+    for check in &gate.required_checks {
+        if !card.checks_passed.contains(check) {
+            failures.push(format!("required check '{}' has not passed", check));
+        }
+    }
+    failures
+}
+
+fn format_card(model: &RegisteredModel, version: &ModelVersion) -> String {
+    let card = &version.card;
+    let mut out = format!("Model card: {} v{}\n", model.name, version.version);
+    let stages = model.stages_of(version.version);
+    if !stages.is_empty() {
+        out.push_str(&format!("  Stages: {}\n", stages.join(", ")));
+    }
+    out.push_str(&format!("  Artifact: {}\n", version.artifact));
+    out.push_str(&format!(
+        "  Created: {} by {}\n",
+        card.created_at.format("%Y-%m-%d %H:%M UTC"),
+        card.author.as_deref().unwrap_or("unknown")
+    ));
+    out.push_str(&format!(
+        "  Dataset: {}{}\n",
+        card.dataset.as_deref().unwrap_or("not recorded"),
+        card.dataset_hash
+            .as_deref()
+            .map(|h| format!(" (sha256 {})", short(h)))
+            .unwrap_or_default()
+    ));
+    out.push_str(&format!(
+        "  Commit: {}\n",
+        card.commit.as_deref().unwrap_or("not recorded")
+    ));
+    if let Some(id) = &card.experiment_id {
+        out.push_str(&format!("  Experiment run: {}\n", id));
+    }
+    if card
+        .hyperparameters
+        .as_object()
+        .is_some_and(|h| !h.is_empty())
+    {
+        out.push_str(&format!("  Hyperparameters: {}\n", card.hyperparameters));
+    }
+    if !card.metrics.is_empty() {
+        let metrics: Vec<String> = card
+            .metrics
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        out.push_str(&format!("  Metrics: {}\n", metrics.join(", ")));
+    }
+    if !card.checks_passed.is_empty() {
+        out.push_str(&format!(
+            "  Checks passed: {}\n",
+            card.checks_passed.join(", ")
+        ));
+    }
+    out.trim_end().to_string()
+}
+
+fn short(hash: &str) -> &str {
+    &hash[..hash.len().min(12)]
+}
+
+fn hash_file(path: &Path) -> Option<String> {
+    if !path.is_file() {
+        return None;
+    }
+    let data = std::fs::read(path).ok()?;
+    let mut hasher = Sha256::new();
+    hasher.update(&data);
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+fn git(workspace: &Path, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(workspace)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|s| !s.is_empty())
+}
+
+// ---------------------------------------------------------------------------
+// Tool trait implementation
+// ---------------------------------------------------------------------------
+
+#[async_trait]
+impl Tool for ModelRegistryTool {
+    fn name(&self) -> &str {
+        "model_registry"
+    }
+
+    fn description(&self) -> &str {
+        "Version trained models with model cards (dataset and hash, commit, hyperparameters, eval metrics, author), promote them through dev → staging → production behind configured gates, roll a stage back, and trace lineage to the dataset and experiment run. Refer to a stage with name@stage, e.g. churn@production. Actions: register, card, record_check, promote, rollback, resolve, lineage, history, list, delete."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": [
+                        "register", "card", "record_check", "promote", "rollback",
+                        "resolve", "lineage", "history", "list", "delete"
+                    ],
+                    "description": "Action to perform"
+                },
+                "name": { "type": "string", "description": "Model name, optionally with a stage alias (churn@production)" },
+                "version": { "type": "string", "description": "Version number or stage name (default: latest version)" },
+                "stage": { "type": "string", "enum": STAGES, "description": "Stage to promote to or roll back" },
+                "artifact": { "type": "string", "description": "Path or URI of the saved model (for register)" },
+                "dataset": { "type": "string", "description": "Training dataset reference; workspace files are hashed" },
+                "dataset_hash": { "type": "string", "description": "Dataset SHA-256, when the dataset is not a workspace file" },
+                "commit": { "type": "string", "description": "Code commit (default: the workspace's git HEAD)" },
+                "hyperparameters": { "type": "object", "description": "Training hyperparameters" },
+                "metrics": { "type": "object", "description": "Numeric eval metrics, e.g. {\"accuracy\": 0.93}" },
+                "checks_passed": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Checks already passed, e.g. transparency, safety"
+                },
+                "check": { "type": "string", "description": "Check that passed (for record_check)" },
+                "experiment_id": { "type": "string", "description": "Experiment tracker run that produced the model" },
+                "author": { "type": "string", "description": "Author (default: git user.name)" },
+                "reason": { "type": "string", "description": "Why the stage changed (for promote and rollback)" }
+            },
+            "required": ["action"]
+        })
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Write
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolOutput, ToolError> {
+        let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("");
+
+        match action {
+            "register" => self.action_register(&args),
+            "card" => self.action_card(&args),
+            "record_check" => self.action_record_check(&args),
+            "promote" => self.action_promote(&args),
+            "rollback" => self.action_rollback(&args),
+            "resolve" => self.action_resolve(&args),
+            "lineage" => self.action_lineage(&args),
+            "history" => self.action_history(&args),
+            "list" => self.action_list(),
+            "delete" => self.action_delete(&args),
+            _ => Ok(ToolOutput::text(format!(
+                "Unknown action: '{}'. Use: register, card, record_check, promote, rollback, resolve, lineage, history, list, delete",
+                action
+            ))),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn make_tool() -> (TempDir, ModelRegistryTool) {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        (dir, ModelRegistryTool::new(workspace))
+    }
+
+    async fn run(tool: &ModelRegistryTool, args: Value) -> String {
+        tool.execute(args).await.unwrap().content
+    }
+
+    #[tokio::test]
+    async fn test_promote_rollback_and_guarded_delete() {
+        let (_dir, tool) = make_tool();
+        for accuracy in [0.91, 0.95] {
+            run(
+                &tool,
+                json!({"action": "register", "name": "churn", "artifact": "models/churn.onnx",
+                       "metrics": {"accuracy": accuracy}}),
+            )
+            .await;
+        }
+        let out = run(
+            &tool,
+            json!({"action": "promote", "name": "churn", "version": 1, "stage": "staging"}),
+        )
+        .await;
+        assert!(out.contains("must be in dev"), "{}", out);
+
+        for version in [1, 2] {
+            for stage in STAGES {
+                run(
+                    &tool,
+                    json!({"action": "promote", "name": "churn", "version": version, "stage": stage}),
+                )
+                .await;
+            }
+        }
+        let out = run(
+            &tool,
+            json!({"action": "resolve", "name": "churn@production"}),
+        )
+        .await;
+        assert_eq!(out, "churn v2: models/churn.onnx");
+
+        let out = run(
+            &tool,
+            json!({"action": "delete", "name": "churn", "version": 2}),
+        )
+        .await;
+        assert!(out.contains("Refusing"), "{}", out);
+        assert!(out.contains("production"));
+
+        let out = run(
+            &tool,
+            json!({"action": "rollback", "name": "churn", "stage": "production", "reason": "bad canary"}),
+        )
+        .await;
+        assert!(out.contains("back to v1"), "{}", out);
+        let out = run(
+            &tool,
+            json!({"action": "resolve", "name": "churn@production"}),
+        )
+        .await;
+        assert!(out.starts_with("churn v1"));
+        let out = run(
+            &tool,
+            json!({"action": "rollback", "name": "churn", "stage": "production"}),
+        )
+        .await;
+        assert!(out.contains("no earlier version"), "{}", out);
+
+        let history = tool.load_history();
+        assert_eq!(history.len(), 7);
+        assert_eq!(history[6].change, StageChange::Rollback);
+        assert_eq!((history[6].from, history[6].to), (Some(2), Some(1)));
+        assert_eq!(history[6].reason.as_deref(), Some("bad canary"));
+    }
+
+    #[tokio::test]
+    async fn test_lineage_walks_to_dataset_and_experiment() {
+        let (dir, tool) = make_tool();
+        std::fs::write(dir.path().join("train.csv"), "a,b\n1,2\n").unwrap();
+        let experiments = dir.path().join(".rustant/experiments");
+        std::fs::create_dir_all(&experiments).unwrap();
+        std::fs::write(
+            experiments.join("tracker.json"),
+            json!({"experiments": [{"id": "e3", "name": "xgb sweep", "status": "Completed",
+                                    "metrics": {"auc": 0.88}, "hypothesis_id": "h1"}]})
+            .to_string(),
+        )
+        .unwrap();
+        run(
+            &tool,
+            json!({"action": "register", "name": "churn", "artifact": "m.bin",
+                   "dataset": "train.csv", "experiment_id": "e3", "commit": "abc123"}),
+        )
+        .await;
+
+        let out = run(&tool, json!({"action": "lineage", "name": "churn"})).await;
+        assert!(out.contains("Dataset: train.csv"), "{}", out);
+        assert!(out.contains("unchanged since registration"));
+        assert!(out.contains("Commit: abc123"));
+        assert!(out.contains("e3 — xgb sweep [Completed]"));
+        assert!(out.contains("hypothesis: h1"));
+
+        std::fs::write(dir.path().join("train.csv"), "a,b\n9,9\n").unwrap();
+        let out = run(
+            &tool,
+            json!({"action": "lineage", "name": "churn", "version": "1"}),
+        )
+        .await;
+        assert!(out.contains("CHANGED since registration"), "{}", out);
+    }
+
+    #[test]
+    fn test_gate_failures() {
+        let gate = PromotionGate {
+            min_metrics: [("accuracy".to_string(), 0.9)].into(),
+            max_metrics: [("loss".to_string(), 0.2)].into(),
+            required_checks: vec!["safety".to_string(), "transparency".to_string()],
+        };
+        let mut card = ModelCard {
+            dataset: None,
+            dataset_hash: None,
+            commit: None,
+            hyperparameters: json!({}),
+            metrics: [("accuracy".to_string(), 0.85)].into(),
+            checks_passed: vec!["safety".to_string()],
+            experiment_id: None,
+            author: None,
+            created_at: Utc::now(),
+        };
+        let failures = gate_failures(&gate, &card);
+        assert_eq!(
+            failures,
+            vec![
+                "accuracy = 0.85 is below the minimum 0.9",
+                "metric 'loss' is not on the model card",
+                "required check 'transparency' has not passed",
+            ]
+        );
+        card.metrics = [("accuracy".to_string(), 0.93), ("loss".to_string(), 0.1)].into();
+        card.checks_passed.push("transparency".to_string());
+        assert!(gate_failures(&gate, &card).is_empty());
+    }
+}
@@ -0,0 +1,990 @@
+//! Paper sources — unified search across arXiv, Semantic Scholar, and OpenReview.
+//!
+//! Each backend's results are normalized into a [`PaperRecord`], merged across
+//! sources (DOI / arXiv id match first, then fuzzy title + first author + year),
+//! filtered, and ranked. Every merged record carries a stable id (`doi:…`,
+//! `arxiv:…`, or `paper:…`) that follow-up actions can use to reference it.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::arxiv_api::{ArxivClient, ArxivPaper, ArxivSearchParams, escape_bibtex};
+
+const SEMANTIC_SCHOLAR_SEARCH: &str = "https://api.semanticscholar.org/graph/v1/paper/search";
+const SEMANTIC_SCHOLAR_FIELDS: &str =
+    "title,authors,year,venue,externalIds,abstract,citationCount,openAccessPdf";
+const OPENREVIEW_SEARCH: &str = "https://api2.openreview.net/notes/search";
+const USER_AGENT: &str = "Rustant/1.0 (https://github.com/rustant)";
+
+/// DOI prefix arXiv assigns to its own preprints.
+const ARXIV_DOI_PREFIX: &str = "10.48550/";
+
+/// Minimum title-token Jaccard similarity for a fuzzy match.
+const TITLE_SIMILARITY: f64 = 0.85;
+
+/// A backend that can be searched for papers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaperSource {
+    Arxiv,
+    SemanticScholar,
+    #[serde(rename = "openreview")]
+    OpenReview,
+}
+
+impl PaperSource {
+    pub const ALL: [PaperSource; 3] = [
+        PaperSource::Arxiv,
+        PaperSource::SemanticScholar,
+        PaperSource::OpenReview,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaperSource::Arxiv => "arxiv",
+            PaperSource::SemanticScholar => "semantic_scholar",
+            PaperSource::OpenReview => "openreview",
+        }
+    }
+
+    pub fn from_str_loose(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "arxiv" => Some(PaperSource::Arxiv),
+            "semantic_scholar" | "semanticscholar" | "s2" => Some(PaperSource::SemanticScholar),
+            "openreview" | "open_review" => Some(PaperSource::OpenReview),
+            _ => None,
+        }
+    }
+}
+
+/// Ordering applied to merged search results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperRanking {
+    /// Best position any source gave the paper.
+    Relevance,
+    /// Highest citation count first; unknown counts last.
+    Citations,
+    /// Newest year first; unknown years last.
+    Recency,
+}
+
+impl PaperRanking {
+    pub fn from_str_loose(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "citations" | "cited" | "citation_count" => PaperRanking::Citations,
+            "recency" | "recent" | "date" | "year" => PaperRanking::Recency,
+            _ => PaperRanking::Relevance,
+        }
+    }
+}
+
+/// A paper normalized from any backend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperRecord {
+    pub id: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub venue: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arxiv_id: Option<String>,
+    #[serde(rename = "abstract", default)]
+    pub abstract_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citation_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdf_url: Option<String>,
+    pub sources: Vec<PaperSource>,
+    /// Best (lowest) position among the sources that returned this paper.
+    #[serde(skip)]
+    pub relevance: usize,
+}
+
+impl PaperRecord {
+    /// Whether this record describes a published version rather than a preprint.
+    pub fn is_published(&self) -> bool {
+        let venue_published = self.venue.as_deref().is_some_and(|v| {
+            let v = v.to_lowercase();
+            !v.is_empty() && !v.contains("arxiv") && v != "corr"
+        });
+        venue_published || self.publisher_doi().is_some()
+    }
+
+    /// The DOI, unless it is one arXiv minted for the preprint itself.
+    fn publisher_doi(&self) -> Option<&str> {
+        self.doi
+            .as_deref()
+            .filter(|d| !d.to_lowercase().starts_with(ARXIV_DOI_PREFIX))
+    }
+
+    /// Whether `reference` names this record (its id, DOI, or arXiv id).
+    pub fn matches_reference(&self, reference: &str) -> bool {
+        let reference = reference.trim().to_lowercase();
+        if self.id.to_lowercase() == reference {
+            return true;
+        }
+        let bare = reference
+            .strip_prefix("doi:")
+            .or_else(|| reference.strip_prefix("arxiv:"))
+            .unwrap_or(&reference);
+        self.doi
+            .as_deref()
+            .is_some_and(|d| d.to_lowercase() == bare)
+            || self
+                .arxiv_id
+                .as_deref()
+                .is_some_and(|a| strip_arxiv_version(a).to_lowercase() == strip_arxiv_version(bare))
+    }
+
+    /// Generate a BibTeX entry for the record.
+    pub fn to_bibtex(&self) -> String {
+        let first_author = self
+            .authors
+            .first()
+            .and_then(|a| a.split_whitespace().last())
+            .unwrap_or("unknown")
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>();
+        let year = self.year.map(|y| y.to_string()).unwrap_or_default();
+        let title_word = self
+            .title
+            .split_whitespace()
+            .find(|w| w.len() > 3 && w.chars().next().is_some_and(|c| c.is_alphabetic()))
+            .unwrap_or("paper")
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>();
+        let kind = if self.is_published() {
+            "inproceedings"
+        } else {
+            "article"
+        };
+
+        let mut entry = format!(
+            "@{}{{{}{}{},\n  title = {{{}}},\n  author = {{{}}}",
+            kind,
+            first_author,
+            year,
+            title_word,
+            escape_bibtex(&self.title),
+            self.authors.join(" and "),
+        );
+        if !year.is_empty() {
+            entry.push_str(&format!(",\n  year = {{{}}}", year));
+        }
+        if let Some(venue) = self.venue.as_deref().filter(|_| self.is_published()) {
+            entry.push_str(&format!(",\n  booktitle = {{{}}}", escape_bibtex(venue)));
+        }
+        if let Some(doi) = &self.doi {
+            entry.push_str(&format!(",\n  doi = {{{}}}", doi));
+        }
+        if let Some(arxiv_id) = &self.arxiv_id {
+            entry.push_str(&format!(
+                ",\n  eprint = {{{}}},\n  archivePrefix = {{arXiv}}",
+                arxiv_id
+            ));
+        }
+        if let Some(url) = &self.pdf_url {
+            entry.push_str(&format!(",\n  url = {{{}}}", url));
+        }
+        entry.push_str("\n}");
+        entry
+    }
+}
+
+/// Filters applied after merging.
+#[derive(Debug, Clone, Default)]
+pub struct PaperFilters {
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+    /// Case-insensitive substrings; a record matches if its venue contains any.
+    pub venues: Vec<String>,
+    pub min_citations: Option<u64>,
+}
+
+impl PaperFilters {
+    /// Whether `record` passes every filter. Records missing a filtered
+    /// field (e.g. no known year while a year range is set) are excluded.
+    pub fn matches(&self, record: &PaperRecord) -> bool {
+        if self.year_from.is_some() || self.year_to.is_some() {
+            let Some(year) = record.year else {
+                return false;
+            };
+            if self.year_from.is_some_and(|from| year < from)
+                || self.year_to.is_some_and(|to| year > to)
+            {
+                return false;
+            }
+        }
+        if !self.venues.is_empty() {
+            let Some(venue) = record.venue.as_deref().map(str::to_lowercase) else {
+                return false;
+            };
+            if !self
+                .venues
+                .iter()
+                .any(|v| venue.contains(&v.to_lowercase()))
+            {
+                return false;
+            }
+        }
+        if let Some(min) = self.min_citations
+            && record.citation_count.unwrap_or(0) < min
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// A backend that was queried but contributed no results.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedSource {
+    pub source: PaperSource,
+    pub reason: String,
+}
+
+/// Merged results plus any backends that failed.
+#[derive(Debug, Clone, Default)]
+pub struct PaperSearchOutcome {
+    pub records: Vec<PaperRecord>,
+    pub skipped: Vec<SkippedSource>,
+}
+
+// ── Normalization ─────────────────────────────────────────────
+
+/// Strip a trailing version suffix such as `v3` from an arXiv id.
+pub fn strip_arxiv_version(id: &str) -> &str {
+    match id.rfind('v') {
+        Some(pos)
+            if pos > 0
+                && pos + 1 < id.len()
+                && id[pos + 1..].chars().all(|c| c.is_ascii_digit()) =>
+        {
+            &id[..pos]
+        }
+        _ => id,
+    }
+}
+
+/// Normalize an arXiv API paper.
+pub fn from_arxiv(paper: ArxivPaper, position: usize) -> PaperRecord {
+    PaperRecord {
+        id: String::new(),
+        year: paper.published.get(..4).and_then(|y| y.parse().ok()),
+        venue: paper.journal_ref.clone(),
+        doi: paper.doi.clone(),
+        arxiv_id: Some(strip_arxiv_version(&paper.arxiv_id).to_string()),
+        citation_count: None,
+        pdf_url: (!paper.pdf_url.is_empty()).then_some(paper.pdf_url),
+        title: paper.title,
+        authors: paper.authors,
+        abstract_text: paper.summary,
+        sources: vec![PaperSource::Arxiv],
+        relevance: position,
+    }
+}
+
+/// Parse a Semantic Scholar `/paper/search` response.
+pub fn parse_semantic_scholar(body: &Value) -> Vec<PaperRecord> {
+    let Some(data) = body.get("data").and_then(|d| d.as_array()) else {
+        return Vec::new();
+    };
+    data.iter()
+        .enumerate()
+        .filter_map(|(position, paper)| {
+            let title = paper.get("title")?.as_str()?.trim().to_string();
+            let ids = paper.get("externalIds");
+            let external = |key: &str| {
+                ids.and_then(|i| i.get(key))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            };
+            Some(PaperRecord {
+                id: String::new(),
+                title,
+                authors: paper
+                    .get("authors")
+                    .and_then(|a| a.as_array())
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|a| a.get("name")?.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                year: paper.get("year").and_then(|y| y.as_i64()).map(|y| y as i32),
+                venue: paper
+                    .get("venue")
+                    .and_then(|v| v.as_str())
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string),
+                doi: external("DOI"),
+                arxiv_id: external("ArXiv"),
+                abstract_text: paper
+                    .get("abstract")
+                    .and_then(|a| a.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                citation_count: paper.get("citationCount").and_then(|c| c.as_u64()),
+                pdf_url: paper
+                    .get("openAccessPdf")
+                    .and_then(|p| p.get("url"))
+                    .and_then(|u| u.as_str())
+                    .filter(|u| !u.is_empty())
+                    .map(str::to_string),
+                sources: vec![PaperSource::SemanticScholar],
+                relevance: position,
+            })
+        })
+        .collect()
+}
+
+/// Parse an OpenReview (API v2) `/notes/search` response.
+pub fn parse_openreview(body: &Value) -> Vec<PaperRecord> {
+    let Some(notes) = body.get("notes").and_then(|n| n.as_array()) else {
+        return Vec::new();
+    };
+    notes
+        .iter()
+        .enumerate()
+        .filter_map(|(position, note)| {
+            let content = note.get("content")?;
+            // API v2 wraps every field as {"value": …}; v1 used bare values.
+            let field = |key: &str| {
+                content
+                    .get(key)
+                    .map(|f| f.get("value").unwrap_or(f))
+                    .cloned()
+            };
+            let title = field("title")?.as_str()?.trim().to_string();
+            let id = note
+                .get("forum")
+                .or_else(|| note.get("id"))
+                .and_then(|i| i.as_str())?;
+            let timestamp = note
+                .get("pdate")
+                .or_else(|| note.get("cdate"))
+                .and_then(|t| t.as_i64());
+            Some(PaperRecord {
+                id: String::new(),
+                title,
+                authors: field("authors")
+                    .and_then(|a| a.as_array().cloned())
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|a| a.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                year: timestamp
+                    .and_then(chrono::DateTime::from_timestamp_millis)
+                    .map(|d| chrono::Datelike::year(&d)),
+                venue: field("venue")
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .filter(|v| !v.is_empty() && !v.to_lowercase().contains("submitted to")),
+                doi: None,
+                arxiv_id: None,
+                abstract_text: field("abstract")
+                    .and_then(|a| a.as_str().map(str::to_string))
+                    .unwrap_or_default(),
+                citation_count: None,
+                pdf_url: field("pdf")
+                    .and_then(|p| p.as_str().map(str::to_string))
+                    .map(|_| format!("https://openreview.net/pdf?id={}", id)),
+                sources: vec![PaperSource::OpenReview],
+                relevance: position,
+            })
+        })
+        .collect()
+}
+
+// ── Deduplication ─────────────────────────────────────────────
+
+/// Lowercased alphanumeric tokens of a title.
+fn title_tokens(title: &str) -> HashSet<String> {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Jaccard similarity of two titles' token sets.
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (title_tokens(a), title_tokens(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+fn first_author_surname(record: &PaperRecord) -> Option<String> {
+    record
+        .authors
+        .first()
+        .and_then(|a| a.split_whitespace().last())
+        .map(|s| {
+            s.to_lowercase()
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect()
+        })
+}
+
+/// Whether two records describe the same work.
+pub fn same_paper(a: &PaperRecord, b: &PaperRecord) -> bool {
+    if let (Some(x), Some(y)) = (&a.doi, &b.doi)
+        && x.eq_ignore_ascii_case(y)
+    {
+        return true;
+    }
+    if let (Some(x), Some(y)) = (&a.arxiv_id, &b.arxiv_id)
+        && strip_arxiv_version(x).eq_ignore_ascii_case(strip_arxiv_version(y))
+    {
+        return true;
+    }
+    // Two different publisher DOIs are two different works.
+    if let (Some(x), Some(y)) = (a.publisher_doi(), b.publisher_doi())
+        && !x.eq_ignore_ascii_case(y)
+    {
+        return false;
+    }
+    // Preprints are often a year ahead of the published version.
+    let years_close = match (a.year, b.year) {
+        (Some(x), Some(y)) => (x - y).abs() <= 1,
+        _ => true,
+    };
+    years_close
+        && first_author_surname(a) == first_author_surname(b)
+        && title_similarity(&a.title, &b.title) >= TITLE_SIMILARITY
+}
+
+/// Fold `other` into `target`, keeping the published record's metadata and
+/// the preprint's arXiv link.
+fn merge_into(target: &mut PaperRecord, mut other: PaperRecord) {
+    if other.is_published() && !target.is_published() {
+        std::mem::swap(target, &mut other);
+    }
+    if target.publisher_doi().is_none() && other.publisher_doi().is_some() {
+        target.doi = other.doi.take();
+    }
+    target.doi = target.doi.take().or(other.doi);
+    target.arxiv_id = target.arxiv_id.take().or(other.arxiv_id);
+    target.year = target.year.or(other.year);
+    target.venue = target.venue.take().or(other.venue);
+    target.pdf_url = target.pdf_url.take().or(other.pdf_url);
+    target.citation_count = match (target.citation_count, other.citation_count) {
+        (Some(x), Some(y)) => Some(x.max(y)),
+        (x, y) => x.or(y),
+    };
+    if target.abstract_text.is_empty() {
+        target.abstract_text = other.abstract_text;
+    }
+    if target.authors.is_empty() {
+        target.authors = other.authors;
+    }
+    for source in other.sources {
+        if !target.sources.contains(&source) {
+            target.sources.push(source);
+        }
+    }
+    target.relevance = target.relevance.min(other.relevance);
+}
+
+/// 64-bit FNV-1a, used for ids that must not change between releases.
+fn fnv1a(data: &str) -> u64 {
+    data.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The stable id of a record: publisher DOI, then arXiv id, then a hash of
+/// the normalized title and year.
+pub fn stable_id(record: &PaperRecord) -> String {
+    if let Some(doi) = record.publisher_doi() {
+        return format!("doi:{}", doi.to_lowercase());
+    }
+    if let Some(arxiv_id) = &record.arxiv_id {
+        return format!("arxiv:{}", strip_arxiv_version(arxiv_id));
+    }
+    let mut tokens: Vec<_> = title_tokens(&record.title).into_iter().collect();
+    tokens.sort();
+    let key = format!("{}|{}", tokens.join(" "), record.year.unwrap_or_default());
+    format!("paper:{:016x}", fnv1a(&key))
+}
+
+/// Merge duplicate records across sources and assign stable ids.
+pub fn dedup(records: Vec<PaperRecord>) -> Vec<PaperRecord> {
+    let mut merged: Vec<PaperRecord> = Vec::new();
+    for record in records {
+        match merged.iter_mut().find(|m| same_paper(m, &record)) {
+            Some(existing) => merge_into(existing, record),
+            None => merged.push(record),
+        }
+    }
+    for record in &mut merged {
+        record.id = stable_id(record);
+    }
+    merged
+}
+
+/// Sort records in place by the given ranking.
+pub fn rank(records: &mut [PaperRecord], ranking: PaperRanking) {
+    match ranking {
+        PaperRanking::Relevance => records.sort_by_key(|r| {
+            let source_order = r.sources.first().map(|s| *s as usize).unwrap_or(0);
+            (r.relevance, source_order)
+        }),
+        PaperRanking::Citations => records.sort_by_key(|r| std::cmp::Reverse(r.citation_count)),
+        PaperRanking::Recency => records.sort_by_key(|r| std::cmp::Reverse(r.year)),
+    }
+}
+
+// ── Client ────────────────────────────────────────────────────
+
+/// HTTP client that fans a query out to several paper backends.
+pub struct PaperSourcesClient {
+    client: reqwest::Client,
+    arxiv: ArxivClient,
+    semantic_scholar_key: Option<String>,
+}
+
+impl PaperSourcesClient {
+    /// Create a client. A `SEMANTIC_SCHOLAR_API_KEY` in the environment raises
+    /// the Semantic Scholar rate limit but is not required.
+    pub fn new() -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        Ok(Self {
+            client,
+            arxiv: ArxivClient::new()?,
+            semantic_scholar_key: std::env::var("SEMANTIC_SCHOLAR_API_KEY")
+                .ok()
+                .filter(|k| !k.is_empty()),
+        })
+    }
+
+    /// Query every source in `sources` concurrently and merge the results.
+    /// A failing source is reported in [`PaperSearchOutcome::skipped`]
+    /// instead of failing the whole search.
+    pub async fn search(
+        &self,
+        query: &str,
+        sources: &[PaperSource],
+        limit: usize,
+    ) -> PaperSearchOutcome {
+        let enabled = |s: PaperSource| sources.contains(&s);
+        let (arxiv, semantic_scholar, openreview) = tokio::join!(
+            async {
+                if enabled(PaperSource::Arxiv) {
+                    Some(self.search_arxiv(query, limit).await)
+                } else {
+                    None
+                }
+            },
+            async {
+                if enabled(PaperSource::SemanticScholar) {
+                    Some(self.search_semantic_scholar(query, limit).await)
+                } else {
+                    None
+                }
+            },
+            async {
+                if enabled(PaperSource::OpenReview) {
+                    Some(self.search_openreview(query, limit).await)
+                } else {
+                    None
+                }
+            },
+        );
+
+        let mut outcome = PaperSearchOutcome::default();
+        let mut records = Vec::new();
+        for (source, result) in [
+            (PaperSource::Arxiv, arxiv),
+            (PaperSource::SemanticScholar, semantic_scholar),
+            (PaperSource::OpenReview, openreview),
+        ] {
+            match result {
+                Some(Ok(found)) => records.extend(found),
+                Some(Err(reason)) => {
+                    tracing::warn!(source = source.as_str(), "Paper search skipped: {}", reason);
+                    outcome.skipped.push(SkippedSource { source, reason });
+                }
+                None => {}
+            }
+        }
+        outcome.records = dedup(records);
+        outcome
+    }
+
+    async fn search_arxiv(&self, query: &str, limit: usize) -> Result<Vec<PaperRecord>, String> {
+        let params = ArxivSearchParams {
+            query: query.to_string(),
+            max_results: limit,
+            ..Default::default()
+        };
+        let result = self.arxiv.search(&params).await?;
+        Ok(result
+            .papers
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| from_arxiv(p, i))
+            .collect())
+    }
+
+    async fn search_semantic_scholar(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<PaperRecord>, String> {
+        let mut request = self.client.get(SEMANTIC_SCHOLAR_SEARCH).query(&[
+            ("query", query),
+            ("limit", &limit.min(100).to_string()),
+            ("fields", SEMANTIC_SCHOLAR_FIELDS),
+        ]);
+        if let Some(key) = &self.semantic_scholar_key {
+            request = request.header("x-api-key", key);
+        }
+        let body = fetch_json(request, "Semantic Scholar").await?;
+        Ok(parse_semantic_scholar(&body))
+    }
+
+    async fn search_openreview(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<PaperRecord>, String> {
+        let request = self.client.get(OPENREVIEW_SEARCH).query(&[
+            ("term", query),
+            ("type", "terms"),
+            ("content", "all"),
+            ("source", "forum"),
+            ("limit", &limit.min(100).to_string()),
+        ]);
+        let body = fetch_json(request, "OpenReview").await?;
+        Ok(parse_openreview(&body))
+    }
+}
+
+async fn fetch_json(request: reqwest::RequestBuilder, backend: &str) -> Result<Value, String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("{} request failed: {}", backend, e))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(format!("{} rate limit reached (HTTP 429)", backend));
+    }
+    if !status.is_success() {
+        return Err(format!("{} returned status {}", backend, status));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse {} response: {}", backend, e))
+}
+
+// ── Tests ─────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(title: &str, author: &str, year: i32, source: PaperSource) -> PaperRecord {
+        PaperRecord {
+            id: String::new(),
+            title: title.to_string(),
+            authors: vec![author.to_string()],
+            year: Some(year),
+            venue: None,
+            doi: None,
+            arxiv_id: None,
+            abstract_text: String::new(),
+            citation_count: None,
+            pdf_url: None,
+            sources: vec![source],
+            relevance: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_semantic_scholar() {
+        let body = json!({
+            "total": 1,
+            "data": [{
+                "paperId": "204e3073870fae3d05bcbc2f6a8e263d9b72e776",
+                "title": "Attention is All you Need",
+                "authors": [{"name": "Ashish Vaswani"}, {"name": "Noam Shazeer"}],
+                "year": 2017,
+                "venue": "Neural Information Processing Systems",
+                "externalIds": {"DOI": "10.5555/3295222.3295349", "ArXiv": "1706.03762"},
+                "abstract": "The dominant sequence transduction models...",
+                "citationCount": 100000,
+                "openAccessPdf": null
+            }]
+        });
+        let records = parse_semantic_scholar(&body);
+        assert_eq!(records.len(), 1);
+        let r = &records[0];
+        assert_eq!(r.authors, vec!["Ashish Vaswani", "Noam Shazeer"]);
+        assert_eq!(r.year, Some(2017));
+        assert_eq!(r.arxiv_id.as_deref(), Some("1706.03762"));
+        assert_eq!(r.citation_count, Some(100000));
+        assert!(r.pdf_url.is_none());
+        assert!(r.is_published());
+    }
+
+    #[test]
+    fn test_parse_openreview() {
+        let body = json!({
+            "notes": [{
+                "id": "abc123",
+                "forum": "abc123",
+                "pdate": 1_673_000_000_000_i64,
+                "content": {
+                    "title": {"value": "Sparse Mixtures Revisited"},
+                    "authors": {"value": ["Jane Doe"]},
+                    "abstract": {"value": "We revisit sparse mixtures."},
+                    "venue": {"value": "ICLR 2023 poster"},
+                    "pdf": {"value": "/pdf/deadbeef.pdf"}
+                }
+            }, {
+                "id": "def456",
+                "content": {
+                    "title": {"value": "Under Review"},
+                    "venue": {"value": "Submitted to ICLR 2024"}
+                }
+            }]
+        });
+        let records = parse_openreview(&body);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].year, Some(2023));
+        assert_eq!(records[0].venue.as_deref(), Some("ICLR 2023 poster"));
+        assert_eq!(
+            records[0].pdf_url.as_deref(),
+            Some("https://openreview.net/pdf?id=abc123")
+        );
+        assert!(records[1].venue.is_none());
+        assert!(!records[1].is_published());
+    }
+
+    #[test]
+    fn test_dedup_by_doi() {
+        let mut a = record("A Title", "Ada Lovelace", 2020, PaperSource::Arxiv);
+        a.doi = Some("10.1000/XYZ".into());
+        let mut b = record(
+            "Completely Reworded",
+            "Someone Else",
+            2021,
+            PaperSource::SemanticScholar,
+        );
+        b.doi = Some("10.1000/xyz".into());
+        let merged = dedup(vec![a, b]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].id, "doi:10.1000/xyz");
+        assert_eq!(
+            merged[0].sources,
+            vec![PaperSource::Arxiv, PaperSource::SemanticScholar]
+        );
+    }
+
+    #[test]
+    fn test_dedup_prefers_published_and_keeps_arxiv_link() {
+        let mut preprint = record(
+            "Attention Is All You Need",
+            "Ashish Vaswani",
+            2017,
+            PaperSource::Arxiv,
+        );
+        preprint.arxiv_id = Some("1706.03762".into());
+        preprint.doi = Some("10.48550/arXiv.1706.03762".into());
+        preprint.pdf_url = Some("http://arxiv.org/pdf/1706.03762v7".into());
+        preprint.abstract_text = "The dominant sequence transduction models...".into();
+
+        let mut published = record(
+            "Attention is all you need.",
+            "A. Vaswani",
+            2018,
+            PaperSource::OpenReview,
+        );
+        published.venue = Some("NeurIPS".into());
+        published.relevance = 3;
+
+        let merged = dedup(vec![preprint, published]);
+        assert_eq!(merged.len(), 1);
+        let m = &merged[0];
+        assert_eq!(m.venue.as_deref(), Some("NeurIPS"));
+        assert_eq!(m.year, Some(2018));
+        assert_eq!(m.arxiv_id.as_deref(), Some("1706.03762"));
+        assert_eq!(
+            m.pdf_url.as_deref(),
+            Some("http://arxiv.org/pdf/1706.03762v7")
+        );
+        assert!(!m.abstract_text.is_empty());
+        assert_eq!(m.relevance, 0);
+        assert_eq!(m.id, "arxiv:1706.03762");
+    }
+
+    #[test]
+    fn test_dedup_keeps_distinct_papers() {
+        let a = record(
+            "Deep Residual Learning",
+            "Kaiming He",
+            2016,
+            PaperSource::Arxiv,
+        );
+        // Same title, different first author.
+        let b = record(
+            "Deep Residual Learning",
+            "Jane Doe",
+            2016,
+            PaperSource::OpenReview,
+        );
+        // Same author, years too far apart.
+        let c = record(
+            "Deep Residual Learning",
+            "Kaiming He",
+            2012,
+            PaperSource::OpenReview,
+        );
+        assert_eq!(dedup(vec![a, b, c]).len(), 3);
+    }
+
+    #[test]
+    fn test_stable_id_is_deterministic() {
+        let a = record(
+            "Some Paper: A Study",
+            "Ada Lovelace",
+            2020,
+            PaperSource::OpenReview,
+        );
+        let b = record(
+            "some paper a study",
+            "Other",
+            2020,
+            PaperSource::SemanticScholar,
+        );
+        assert_eq!(stable_id(&a), stable_id(&b));
+        assert!(stable_id(&a).starts_with("paper:"));
+
+        let mut c = a.clone();
+        c.arxiv_id = Some("2301.12345v2".into());
+        assert_eq!(stable_id(&c), "arxiv:2301.12345");
+        assert!(c.matches_reference("arxiv:2301.12345v1"));
+        assert!(c.matches_reference("2301.12345"));
+    }
+
+    #[test]
+    fn test_filters() {
+        let mut r = record("Title", "Author", 2021, PaperSource::SemanticScholar);
+        r.venue = Some("ICML 2021".into());
+        r.citation_count = Some(40);
+
+        let filters = PaperFilters {
+            year_from: Some(2020),
+            year_to: Some(2022),
+            venues: vec!["icml".into(), "NeurIPS".into()],
+            min_citations: Some(10),
+        };
+        assert!(filters.matches(&r));
+        assert!(
+            !PaperFilters {
+                min_citations: Some(50),
+                ..filters.clone()
+            }
+            .matches(&r)
+        );
+        assert!(
+            !PaperFilters {
+                venues: vec!["ACL".into()],
+                ..filters.clone()
+            }
+            .matches(&r)
+        );
+        r.year = None;
+        assert!(!filters.matches(&r));
+        assert!(PaperFilters::default().matches(&r));
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut a = record("A", "X", 2019, PaperSource::Arxiv);
+        a.relevance = 1;
+        a.citation_count = Some(5);
+        let mut b = record("B", "Y", 2023, PaperSource::SemanticScholar);
+        b.relevance = 0;
+        let mut c = record("C", "Z", 2021, PaperSource::Arxiv);
+        c.relevance = 0;
+        c.citation_count = Some(50);
+        let mut records = vec![a, b, c];
+
+        rank(&mut records, PaperRanking::Relevance);
+        let titles: Vec<_> = records.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["C", "B", "A"]);
+
+        rank(&mut records, PaperRanking::Citations);
+        let titles: Vec<_> = records.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["C", "A", "B"]);
+
+        rank(&mut records, PaperRanking::Recency);
+        let titles: Vec<_> = records.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["B", "C", "A"]);
+    }
+
+    #[test]
+    fn test_source_and_ranking_parsing() {
+        assert_eq!(
+            PaperSource::from_str_loose("Semantic Scholar"),
+            Some(PaperSource::SemanticScholar)
+        );
+        assert_eq!(
+            PaperSource::from_str_loose("openreview"),
+            Some(PaperSource::OpenReview)
+        );
+        assert_eq!(PaperSource::from_str_loose("pubmed"), None);
+        assert_eq!(
+            PaperRanking::from_str_loose("citations"),
+            PaperRanking::Citations
+        );
+        assert_eq!(
+            PaperRanking::from_str_loose("recent"),
+            PaperRanking::Recency
+        );
+        assert_eq!(
+            PaperRanking::from_str_loose("anything"),
+            PaperRanking::Relevance
+        );
+    }
+
+    #[test]
+    fn test_record_bibtex() {
+        let mut r = record(
+            "Attention Is All You Need",
+            "Ashish Vaswani",
+            2017,
+            PaperSource::Arxiv,
+        );
+        r.venue = Some("NeurIPS".into());
+        r.arxiv_id = Some("1706.03762".into());
+        let bib = r.to_bibtex();
+        assert!(bib.starts_with("@inproceedings{vaswani2017attention,"));
+        assert!(bib.contains("booktitle = {NeurIPS}"));
+        assert!(bib.contains("eprint = {1706.03762}"));
+    }
+}
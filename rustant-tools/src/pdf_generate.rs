@@ -1,12 +1,13 @@
 //! PDF generation tool — create PDF documents from text/markdown content.
 
 use async_trait::async_trait;
-use genpdf::elements::{Break, Paragraph};
-use genpdf::{Document, SimplePageDecorator};
+use genpdf::elements::{Break, Image, Paragraph};
+use genpdf::{Alignment, Document, SimplePageDecorator};
+use rustant_core::canvas::{ChartImageOptions, ChartSpec, render_chart_png};
 use rustant_core::error::ToolError;
-use rustant_core::types::{RiskLevel, ToolOutput};
+use rustant_core::types::{Artifact, RiskLevel, ToolOutput};
 use serde_json::{Value, json};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::registry::Tool;
 
@@ -18,6 +19,26 @@ impl PdfGenerateTool {
     pub fn new(workspace: PathBuf) -> Self {
         Self { workspace }
     }
+
+    /// Resolve an image source, an attachment handle or a workspace path.
+    fn image_path(&self, source: &str) -> Result<PathBuf, ToolError> {
+        let invalid = |reason: String| ToolError::InvalidArguments {
+            name: "pdf_generate".into(),
+            reason,
+        };
+        if !rustant_core::attachments::is_handle(source) {
+            return Ok(self.workspace.join(source));
+        }
+        let (handle, path) = rustant_core::attachments::resolve(source)
+            .map_err(|e| invalid(format!("images: {}", e)))?;
+        if !matches!(handle.mime_type.as_str(), "image/png" | "image/jpeg") {
+            return Err(invalid(format!(
+                "images: attachment {} is {}; only PNG and JPEG can be embedded",
+                source, handle.mime_type
+            )));
+        }
+        Ok(path)
+    }
 }
 
 #[async_trait]
@@ -26,7 +47,9 @@ impl Tool for PdfGenerateTool {
         "pdf_generate"
     }
     fn description(&self) -> &str {
-        "Generate PDF documents from text content. Provide title, content lines, and output path."
+        "Generate PDF documents from text content. Provide title, content lines, and output path. \
+         Optionally embed charts (canvas chart specs) and images (attachment handles or image \
+         paths) after the text."
     }
     fn parameters_schema(&self) -> Value {
         json!({
@@ -39,7 +62,17 @@ impl Tool for PdfGenerateTool {
                 },
                 "title": { "type": "string", "description": "Document title" },
                 "content": { "type": "string", "description": "Document content (plain text, paragraphs separated by blank lines)" },
-                "output": { "type": "string", "description": "Output file path (e.g., 'report.pdf')" }
+                "output": { "type": "string", "description": "Output file path (e.g., 'report.pdf')" },
+                "charts": {
+                    "type": "array",
+                    "description": "Charts to embed after the content. Each is a chart spec: chart_type, labels, datasets [{label, data, color?, y_axis?}], title?, x_axis?/y_axis?/y2_axis? {type: category|linear|log|time, title?, min?, max?, timezone?, format?}, annotations? [{axis: x|y|y2, value, label?, color?}]",
+                    "items": { "type": "object" }
+                },
+                "images": {
+                    "type": "array",
+                    "description": "Images to embed after the charts: attachment handles (att-...) or PNG/JPEG paths relative to the workspace",
+                    "items": { "type": "string" }
+                }
             },
             "required": ["action", "output"]
         })
@@ -70,6 +103,19 @@ impl Tool for PdfGenerateTool {
             .and_then(|v| v.as_str())
             .unwrap_or("output.pdf");
         let output_path = self.workspace.join(output_str);
+        let charts = parse_charts(&args)?;
+        let images = args
+            .get("images")
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|source| self.image_path(source))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
 
         // Use the built-in font — try several system locations
         let font_family =
@@ -112,6 +158,16 @@ impl Tool for PdfGenerateTool {
             }
         }
 
+        for chart in &charts {
+            doc.push(chart_image(chart)?);
+            doc.push(Break::new(1));
+        }
+
+        for path in &images {
+            doc.push(embedded_image(path)?);
+            doc.push(Break::new(1));
+        }
+
         if let Some(parent) = output_path.parent() {
             std::fs::create_dir_all(parent).ok();
         }
@@ -125,13 +181,85 @@ impl Tool for PdfGenerateTool {
         let size = std::fs::metadata(&output_path)
             .map(|m| m.len())
             .unwrap_or(0);
-        Ok(ToolOutput::text(format!(
-            "Generated PDF: {} ({} bytes)",
-            output_str, size
-        )))
+        Ok(
+            ToolOutput::text(format!("Generated PDF: {} ({} bytes)", output_str, size))
+                .with_artifact(Artifact::FileCreated {
+                    path: PathBuf::from(output_str),
+                }),
+        )
     }
 }
 
+/// Parse and validate the `charts` argument, naming the offending field.
+fn parse_charts(args: &Value) -> Result<Vec<ChartSpec>, ToolError> {
+    let Some(charts) = args.get("charts") else {
+        return Ok(Vec::new());
+    };
+    let invalid = |reason: String| ToolError::InvalidArguments {
+        name: "pdf_generate".into(),
+        reason,
+    };
+    let charts = charts
+        .as_array()
+        .ok_or_else(|| invalid("charts: expected an array of chart specs".into()))?;
+    let mut specs = Vec::with_capacity(charts.len());
+    for (i, chart) in charts.iter().enumerate() {
+        let spec: ChartSpec = serde_json::from_value(chart.clone())
+            .map_err(|e| invalid(format!("charts[{}]: {}", i, e)))?;
+        if let Err(e) = spec.validate() {
+            let issues: Vec<String> = e
+                .issues
+                .iter()
+                .map(|issue| format!("charts[{}].{}: {}", i, issue.field, issue.message))
+                .collect();
+            return Err(invalid(issues.join("; ")));
+        }
+        specs.push(spec);
+    }
+    Ok(specs)
+}
+
+/// Rasterize a chart and load it as a centered PDF image.
+fn chart_image(spec: &ChartSpec) -> Result<Image, ToolError> {
+    let failed = |message: String| ToolError::ExecutionFailed {
+        name: "pdf_generate".into(),
+        message,
+    };
+    // 1600px at 270 DPI is about 15cm, the A4 width inside the 30mm margins.
+    let options = ChartImageOptions {
+        scale: 2.0,
+        ..Default::default()
+    };
+    let png = render_chart_png(spec, &options).map_err(|e| failed(e.to_string()))?;
+    // genpdf only loads images from files or `image` buffers.
+    let path = std::env::temp_dir().join(format!("rustant-chart-{}.png", uuid::Uuid::new_v4()));
+    std::fs::write(&path, &png).map_err(|e| failed(format!("Failed to write chart: {}", e)))?;
+    let image = Image::from_path(&path);
+    std::fs::remove_file(&path).ok();
+    Ok(image
+        .map_err(|e| failed(format!("Failed to load chart image: {}", e)))?
+        .with_alignment(Alignment::Center)
+        .with_dpi(270.0))
+}
+
+/// Load an image file as a centered PDF image no wider than the text column.
+fn embedded_image(path: &Path) -> Result<Image, ToolError> {
+    // About 15cm fits inside the 30mm margins of an A4 page.
+    const MAX_WIDTH_INCHES: f64 = 5.9;
+    let width = std::fs::read(path)
+        .ok()
+        .and_then(|bytes| rustant_core::attachments::image_dimensions(&bytes))
+        .map(|(width, _)| width);
+    let dpi = width.map_or(300.0, |w| (w as f64 / MAX_WIDTH_INCHES).max(300.0));
+    Ok(Image::from_path(path)
+        .map_err(|e| ToolError::ExecutionFailed {
+            name: "pdf_generate".into(),
+            message: format!("Failed to load image {}: {}", path.display(), e),
+        })?
+        .with_alignment(Alignment::Center)
+        .with_dpi(dpi))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,6 +274,35 @@ mod tests {
         assert!(schema.get("properties").is_some());
     }
 
+    #[tokio::test]
+    async fn test_pdf_generate_rejects_invalid_chart() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tool = PdfGenerateTool::new(dir.path().to_path_buf());
+        let err = tool
+            .execute(json!({
+                "action": "generate",
+                "output": "report.pdf",
+                "charts": [
+                    {"chart_type": "line", "labels": ["a"], "datasets": [{"label": "x", "data": [1.0]}]},
+                    {
+                        "chart_type": "line",
+                        "labels": ["2026-01-01", "soon"],
+                        "x_axis": {"type": "time"},
+                        "datasets": [{"label": "x", "data": [1.0, 2.0]}]
+                    }
+                ]
+            }))
+            .await
+            .unwrap_err();
+        match err {
+            ToolError::InvalidArguments { reason, .. } => {
+                assert!(reason.starts_with("charts[1].labels[1]:"), "{reason}");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(!dir.path().join("report.pdf").exists());
+    }
+
     // Note: PDF generation test requires fonts available on the system.
     // The actual render test is platform-dependent.
 }
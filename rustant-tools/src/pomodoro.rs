@@ -1,32 +1,20 @@
 //! Pomodoro timer tool — focus sessions with DND integration on macOS.
+//!
+//! A running pomodoro is a focus block ([`rustant_core::focus`]): Do Not
+//! Disturb is turned on through `macos_focus_mode`, channel messages below
+//! `[focus] hold_below` are held for a digest, and non-critical cron jobs
+//! wait. Stopping the pomodoro reverts all of it.
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use rustant_core::error::ToolError;
+use rustant_core::focus::{FocusBlock, FocusState, InterruptReason};
 use rustant_core::types::{RiskLevel, ToolOutput};
-use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::registry::Tool;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct PomodoroSession {
-    task: String,
-    started_at: DateTime<Utc>,
-    duration_mins: u32,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    completed_at: Option<DateTime<Utc>>,
-    completed: bool,
-}
-
-#[derive(Debug, Default, Serialize, Deserialize)]
-struct PomodoroState {
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    active: Option<PomodoroSession>,
-    history: Vec<PomodoroSession>,
-}
-
 pub struct PomodoroTool {
     workspace: PathBuf,
 }
@@ -36,65 +24,88 @@ impl PomodoroTool {
         Self { workspace }
     }
 
-    fn state_path(&self) -> PathBuf {
-        self.workspace
-            .join(".rustant")
-            .join("pomodoro")
-            .join("state.json")
+    fn save_state(&self, state: &FocusState) -> Result<(), ToolError> {
+        state
+            .save(&self.workspace)
+            .map_err(|e| ToolError::ExecutionFailed {
+                name: "pomodoro".to_string(),
+                message: format!("Failed to write state: {}", e),
+            })
     }
+}
 
-    fn load_state(&self) -> PomodoroState {
-        let path = self.state_path();
-        if path.exists() {
-            std::fs::read_to_string(&path)
-                .ok()
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or_default()
-        } else {
-            PomodoroState::default()
-        }
-    }
+/// Turn Do Not Disturb on unless it already is. Returns whether this call
+/// turned it on, i.e. whether it must be turned off afterwards.
+#[cfg(target_os = "macos")]
+async fn enable_dnd() -> bool {
+    let tool = crate::macos::MacosFocusModeTool;
+    let already_on = tool
+        .execute(json!({"action": "status"}))
+        .await
+        .is_ok_and(|out| out.content.contains("is ON"));
+    !already_on && tool.execute(json!({"action": "enable"})).await.is_ok()
+}
 
-    fn save_state(&self, state: &PomodoroState) -> Result<(), ToolError> {
-        let path = self.state_path();
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| ToolError::ExecutionFailed {
-                name: "pomodoro".to_string(),
-                message: format!("Failed to create state dir: {}", e),
-            })?;
-        }
-        let json = serde_json::to_string_pretty(state).map_err(|e| ToolError::ExecutionFailed {
-            name: "pomodoro".to_string(),
-            message: format!("Failed to serialize state: {}", e),
-        })?;
-        let tmp = path.with_extension("json.tmp");
-        std::fs::write(&tmp, &json).map_err(|e| ToolError::ExecutionFailed {
-            name: "pomodoro".to_string(),
-            message: format!("Failed to write state: {}", e),
-        })?;
-        std::fs::rename(&tmp, &path).map_err(|e| ToolError::ExecutionFailed {
-            name: "pomodoro".to_string(),
-            message: format!("Failed to rename state file: {}", e),
-        })?;
-        Ok(())
+#[cfg(not(target_os = "macos"))]
+async fn enable_dnd() -> bool {
+    // DND not available on non-macOS
+    false
+}
+
+#[cfg(target_os = "macos")]
+async fn disable_dnd() {
+    if let Err(e) = crate::macos::MacosFocusModeTool
+        .execute(json!({"action": "disable"}))
+        .await
+    {
+        tracing::warn!(error = %e, "Failed to turn Do Not Disturb off");
     }
+}
+
+#[cfg(not(target_os = "macos"))]
+async fn disable_dnd() {}
 
-    #[cfg(target_os = "macos")]
-    fn toggle_dnd(enable: bool) {
-        let script = if enable {
-            r#"do shell script "defaults -currentHost write com.apple.notificationcenterui doNotDisturb -boolean true && killall NotificationCenter 2>/dev/null || true""#
-        } else {
-            r#"do shell script "defaults -currentHost write com.apple.notificationcenterui doNotDisturb -boolean false && killall NotificationCenter 2>/dev/null || true""#
-        };
-        let _ = std::process::Command::new("osascript")
-            .args(["-e", script])
-            .output();
+/// Revert what a finished block changed and describe how it went.
+async fn close_block(workspace: &Path, block: &FocusBlock) -> String {
+    let mut text = String::new();
+    if block.dnd {
+        disable_dnd().await;
+        text.push_str(" DND disabled.");
+    }
+    if !block.interruptions.is_empty() {
+        let urgent = block
+            .interruptions
+            .iter()
+            .filter(|i| i.reason == InterruptReason::Urgent)
+            .count();
+        text.push_str(&format!(
+            " {} interruption(s) let through ({} urgent, {} from allowed senders).",
+            block.interruptions.len(),
+            urgent,
+            block.interruptions.len() - urgent
+        ));
+    }
+    if let Some(digest) = block.digest() {
+        let sent = rustant_core::focus::send_digest(workspace, &digest).await;
+        text.push_str(&format!("\n\n{}\n{}", digest.trim_end(), sent));
     }
+    text
+}
 
-    #[cfg(not(target_os = "macos"))]
-    fn toggle_dnd(_enable: bool) {
-        // DND not available on non-macOS
+/// Close a focus block left open past its end, e.g. by a crash, turning Do
+/// Not Disturb back off. Run at startup; returns a notice when a block was
+/// closed.
+pub async fn reconcile_focus(workspace: &Path) -> Option<String> {
+    let mut state = FocusState::load(workspace);
+    let block = state.reconcile(Utc::now())?;
+    if let Err(e) = state.save(workspace) {
+        tracing::warn!(error = %e, "Failed to save focus state");
     }
+    Some(format!(
+        "Focus block '{}' ended while Rustant was not running.{}",
+        block.task,
+        close_block(workspace, &block).await
+    ))
 }
 
 #[async_trait]
@@ -104,7 +115,10 @@ impl Tool for PomodoroTool {
     }
 
     fn description(&self) -> &str {
-        "Pomodoro focus timer with start/stop/status/history. Toggles Do Not Disturb on macOS."
+        "Pomodoro focus timer with start/stop/status/history/report. While a session runs, \
+         Do Not Disturb is on (macOS), non-urgent channel messages are held for a digest and \
+         non-critical scheduled jobs wait. 'report' shows the last week's focus time and \
+         interruptions."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -113,7 +127,7 @@ impl Tool for PomodoroTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["start", "stop", "status", "history"],
+                    "enum": ["start", "stop", "status", "history", "report"],
                     "description": "Action to perform"
                 },
                 "task": {
@@ -124,6 +138,10 @@ impl Tool for PomodoroTool {
                     "type": "integer",
                     "description": "Focus duration in minutes (default: 25)",
                     "default": 25
+                },
+                "dnd": {
+                    "type": "boolean",
+                    "description": "Turn on Do Not Disturb for the session (default: [focus] dnd)"
                 }
             },
             "required": ["action"]
@@ -136,15 +154,10 @@ impl Tool for PomodoroTool {
 
     async fn execute(&self, args: Value) -> Result<ToolOutput, ToolError> {
         let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("");
-        let mut state = self.load_state();
+        let mut state = FocusState::load(&self.workspace);
 
         match action {
             "start" => {
-                if state.active.is_some() {
-                    return Ok(ToolOutput::text(
-                        "A pomodoro session is already active. Stop it first.",
-                    ));
-                }
                 let task = args
                     .get("task")
                     .and_then(|v| v.as_str())
@@ -153,53 +166,61 @@ impl Tool for PomodoroTool {
                     .get("duration_mins")
                     .and_then(|v| v.as_u64())
                     .unwrap_or(25) as u32;
+                if !state.begin(task, duration, Utc::now()) {
+                    return Ok(ToolOutput::text(
+                        "A pomodoro session is already active. Stop it first.",
+                    ));
+                }
+                let config = rustant_core::config::load_config(Some(&self.workspace), None)
+                    .unwrap_or_default()
+                    .focus;
+                let dnd = args
+                    .get("dnd")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(config.dnd);
+                if let Some(block) = state.active.as_mut() {
+                    block.dnd = dnd && enable_dnd().await;
+                }
+                self.save_state(&state)?;
 
-                let session = PomodoroSession {
-                    task: task.to_string(),
-                    started_at: Utc::now(),
-                    duration_mins: duration,
-                    completed_at: None,
-                    completed: false,
+                let mut text = format!("Pomodoro started: '{}' ({} minutes).", task, duration);
+                if state.active.as_ref().is_some_and(|b| b.dnd) {
+                    text.push_str(" DND enabled.");
+                }
+                text.push_str(&format!(
+                    " Channel messages below {:?} priority are held until the end.",
+                    config.hold_below
+                ));
+                Ok(ToolOutput::text(text))
+            }
+            "stop" => {
+                let Some(block) = state.end(Utc::now()) else {
+                    return Ok(ToolOutput::text("No active pomodoro session to stop."));
                 };
-                state.active = Some(session);
                 self.save_state(&state)?;
-                Self::toggle_dnd(true);
-
+                let elapsed = (Utc::now() - block.started_at)
+                    .num_minutes()
+                    .min(block.duration_mins as i64);
                 Ok(ToolOutput::text(format!(
-                    "Pomodoro started: '{}' ({} minutes). DND enabled.",
-                    task, duration
+                    "Pomodoro complete: '{}' after {} minutes.{}",
+                    block.task,
+                    elapsed,
+                    close_block(&self.workspace, &block).await
                 )))
             }
-            "stop" => {
-                if let Some(mut session) = state.active.take() {
-                    session.completed_at = Some(Utc::now());
-                    session.completed = true;
-                    let elapsed = (Utc::now() - session.started_at).num_minutes();
-                    state.history.push(session.clone());
-                    // Keep last 100 entries
-                    if state.history.len() > 100 {
-                        state.history.drain(0..state.history.len() - 100);
-                    }
-                    self.save_state(&state)?;
-                    Self::toggle_dnd(false);
-
-                    Ok(ToolOutput::text(format!(
-                        "Pomodoro complete: '{}' after {} minutes. DND disabled.",
-                        session.task, elapsed
-                    )))
-                } else {
-                    Ok(ToolOutput::text("No active pomodoro session to stop."))
-                }
-            }
             "status" => {
                 if let Some(ref session) = state.active {
                     let elapsed = (Utc::now() - session.started_at).num_minutes();
                     let remaining = session.duration_mins as i64 - elapsed;
                     Ok(ToolOutput::text(format!(
-                        "Active: '{}' — {} min elapsed, {} min remaining",
+                        "Active: '{}' — {} min elapsed, {} min remaining. {}. \
+                         {} message(s) held, {} interruption(s).",
                         session.task,
                         elapsed,
-                        remaining.max(0)
+                        remaining.max(0),
+                        session.status_line(),
+                        session.held.len(),
+                        session.interruptions.len()
                     )))
                 } else {
                     Ok(ToolOutput::text("No active pomodoro session."))
@@ -224,8 +245,9 @@ impl Tool for PomodoroTool {
                     recent.join("\n")
                 )))
             }
+            "report" => Ok(ToolOutput::text(state.weekly_report(Utc::now()))),
             _ => Ok(ToolOutput::text(format!(
-                "Unknown action: {}. Use: start, stop, status, history",
+                "Unknown action: {}. Use: start, stop, status, history, report",
                 action
             ))),
         }
@@ -256,6 +278,35 @@ mod tests {
         assert!(result.content.contains("complete") || result.content.contains("Complete"));
     }
 
+    #[tokio::test]
+    async fn test_pomodoro_stop_delivers_held_messages() {
+        use rustant_core::channels::{ChannelMessage, ChannelType, ChannelUser, MessageClassifier};
+
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let tool = PomodoroTool::new(workspace.clone());
+        tool.execute(json!({"action": "start", "task": "writing", "dnd": false}))
+            .await
+            .unwrap();
+
+        let user = ChannelUser::new("alice", ChannelType::Slack);
+        let msg = ChannelMessage::text(ChannelType::Slack, "C1", user, "lunch at noon?");
+        let classified = MessageClassifier::new(Default::default()).classify(&msg);
+        let mut state = FocusState::load(&workspace);
+        state.screen(&classified, "slack", &Default::default(), Utc::now());
+        state.save(&workspace).unwrap();
+
+        let status = tool.execute(json!({"action": "status"})).await.unwrap();
+        assert!(status.content.contains("1 message(s) held"));
+        let stopped = tool.execute(json!({"action": "stop"})).await.unwrap();
+        assert!(stopped.content.contains("lunch at noon?"));
+        assert!(!stopped.content.contains("DND disabled"));
+
+        let report = tool.execute(json!({"action": "report"})).await.unwrap();
+        assert!(report.content.contains("1 block(s)"));
+        assert!(report.content.contains("1 message(s) held"));
+    }
+
     #[tokio::test]
     async fn test_pomodoro_double_start() {
         let dir = TempDir::new().unwrap();
@@ -482,6 +482,10 @@ impl PrivacyManagerTool {
         let domains = self.list_domains();
 
         for domain in &domains {
+            // Clipboard history stays on this machine, sealed, and is never exported.
+            if domain == rustant_core::clipboard::CLIPBOARD_DIR {
+                continue;
+            }
             let domain_dir = rustant_dir.join(domain);
             let mut domain_files = serde_json::Map::new();
 
@@ -523,7 +527,7 @@ impl PrivacyManagerTool {
         if export_json.len() < 50_000 {
             Ok(ToolOutput::text(format!(
                 "Exported {} domain(s) ({} bytes):\n{}",
-                domains.len(),
+                export.len(),
                 export_json.len(),
                 export_json
             )))
@@ -535,7 +539,7 @@ impl PrivacyManagerTool {
             })?;
             Ok(ToolOutput::text(format!(
                 "Exported {} domain(s) to {}. Size: {}",
-                domains.len(),
+                export.len(),
                 output_path.display(),
                 Self::format_size(export_json.len() as u64)
             )))
@@ -554,6 +558,18 @@ impl PrivacyManagerTool {
             ));
         }
 
+        if domain == "events" {
+            return Ok(ToolOutput::text(match self.delete_events()? {
+                Some(count) => format!("Deleted {} event(s) recorded in this workspace.", count),
+                None => "No event store found.".to_string(),
+            }));
+        }
+        let events = if domain == "all" {
+            self.delete_events()?
+        } else {
+            None
+        };
+
         let rustant_dir = self.rustant_dir();
         if !rustant_dir.exists() {
             return Ok(ToolOutput::text("No .rustant/ directory found."));
@@ -594,12 +610,16 @@ impl PrivacyManagerTool {
                 }
             }
             Ok(ToolOutput::text(format!(
-                "Deleted all data except privacy config. Removed {} item(s) across domain(s): {}",
+                "Deleted all data except privacy config. Removed {} item(s) across domain(s): {}{}",
                 deleted_total,
                 if deleted_domains.is_empty() {
                     "none".to_string()
                 } else {
                     deleted_domains.join(", ")
+                },
+                match events {
+                    Some(count) => format!(". Deleted {} recorded event(s).", count),
+                    None => String::new(),
                 }
             )))
         } else {
@@ -622,6 +642,23 @@ impl PrivacyManagerTool {
         }
     }
 
+    /// Delete this workspace's rows from the event store. `None` when there
+    /// is no store.
+    fn delete_events(&self) -> Result<Option<usize>, ToolError> {
+        let config =
+            rustant_core::config::load_config(Some(&self.workspace), None).unwrap_or_default();
+        let Some(path) = config.event_store.resolved_path().filter(|p| p.exists()) else {
+            return Ok(None);
+        };
+        rustant_core::event_store::EventStore::open(&path)
+            .and_then(|store| store.delete_workspace(&self.workspace.display().to_string()))
+            .map(Some)
+            .map_err(|e| ToolError::ExecutionFailed {
+                name: "privacy_manager".to_string(),
+                message: format!("Failed to delete recorded events: {}", e),
+            })
+    }
+
     fn action_encrypt_store(&self, args: &Value) -> Result<ToolOutput, ToolError> {
         let path_str = args
             .get("path")
@@ -848,7 +885,7 @@ impl Tool for PrivacyManagerTool {
                 },
                 "domain": {
                     "type": "string",
-                    "description": "Domain name or 'all' (for delete_data)"
+                    "description": "Domain name, 'events' (this workspace's recorded events) or 'all' (for delete_data)"
                 },
                 "path": {
                     "type": "string",
@@ -1051,6 +1088,45 @@ mod tests {
         assert!(!domain_dir.exists());
     }
 
+    #[tokio::test]
+    async fn test_delete_data_events() {
+        use rustant_core::event_store::{Event, EventRecord, EventStore};
+
+        let (_dir, workspace) = setup();
+        let db = workspace.join("events.db");
+        std::fs::create_dir_all(workspace.join(".rustant")).unwrap();
+        std::fs::write(
+            workspace.join(".rustant").join("config.toml"),
+            format!("[event_store]\npath = {:?}\n", db.display().to_string()),
+        )
+        .unwrap();
+        let event = |ws: &str| EventRecord {
+            at: Utc::now(),
+            workspace: ws.to_string(),
+            event: Event::Approval {
+                session_id: None,
+                tool: "shell_exec".into(),
+                approved: true,
+            },
+        };
+        EventStore::open(&db)
+            .unwrap()
+            .write(&[event(&workspace.display().to_string()), event("/elsewhere")])
+            .unwrap();
+
+        let tool = PrivacyManagerTool::new(workspace.clone());
+        let result = tool
+            .execute(json!({"action": "delete_data", "domain": "events"}))
+            .await
+            .unwrap();
+        assert!(result.content.contains("Deleted 1 event(s)"));
+        let remaining = EventStore::open_read_only(&db)
+            .unwrap()
+            .query("SELECT workspace FROM approvals")
+            .unwrap();
+        assert_eq!(remaining.rows, vec![vec!["/elsewhere".to_string()]]);
+    }
+
     #[tokio::test]
     async fn test_export_data() {
         let (_dir, workspace) = setup();
@@ -1069,6 +1145,10 @@ mod tests {
         std::fs::create_dir_all(&inbox_dir).unwrap();
         std::fs::write(inbox_dir.join("items.json"), r#"{"items": []}"#).unwrap();
 
+        let clipboard_dir = workspace.join(".rustant").join("clipboard");
+        std::fs::create_dir_all(&clipboard_dir).unwrap();
+        std::fs::write(clipboard_dir.join("history.sealed"), "sealed").unwrap();
+
         let result = tool
             .execute(json!({"action": "export_data"}))
             .await
@@ -1077,6 +1157,7 @@ mod tests {
         assert!(result.content.contains("career"));
         assert!(result.content.contains("inbox"));
         assert!(result.content.contains("learn rust"));
+        assert!(!result.content.contains("history.sealed"));
     }
 
     #[tokio::test]
@@ -0,0 +1,874 @@
+//! Reader-mode article capture into the research library and knowledge graph.
+//!
+//! `macos_safari` (action `capture`) and `browser_capture` hand the current
+//! tab's URL, title and HTML to [`ReaderCapture`]. It extracts the main
+//! content with [`crate::web_extract`], stores it as markdown with YAML
+//! frontmatter under `.rustant/research/captures/`, and records the article,
+//! its author, site and topic tags in the knowledge graph, reusing existing
+//! entities with matching names. Captures are keyed by normalised URL, so
+//! capturing a page again updates its file and graph node instead of adding
+//! new ones. Pages with little or no extractable text (paywalls, empty
+//! shells) still get a file with their metadata and `content_missing: true`.
+//!
+//! Topic tags come from a short LLM call when a provider has been installed
+//! with [`set_topic_tagger`], and from title keywords otherwise.
+
+use crate::browser::BrowserToolContext;
+use crate::knowledge_graph::{ArticleEntry, KnowledgeGraphTool};
+use crate::registry::Tool;
+use crate::web_extract::extract_article;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+use rustant_core::brain::LlmProvider;
+use rustant_core::error::ToolError;
+use rustant_core::types::{CompletionRequest, Message, RiskLevel, ToolOutput};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Directory, relative to the workspace, holding captured articles.
+pub const CAPTURES_DIR: &str = ".rustant/research/captures";
+
+/// Extractions shorter than this are treated as paywalled or empty.
+const MIN_CONTENT_CHARS: usize = 200;
+/// Characters of article text sent to the topic tagger.
+const TAGGER_EXCERPT_CHARS: usize = 2000;
+/// Upper bound on how long capture waits for the topic tagger.
+const TAGGER_TIMEOUT: Duration = Duration::from_secs(8);
+/// Tags kept per capture, counting those passed by the caller.
+const MAX_TAGS: usize = 8;
+
+const STOPWORDS: &[&str] = &[
+    "about",
+    "after",
+    "again",
+    "against",
+    "also",
+    "among",
+    "because",
+    "been",
+    "before",
+    "being",
+    "between",
+    "both",
+    "could",
+    "does",
+    "doing",
+    "during",
+    "each",
+    "from",
+    "have",
+    "having",
+    "here",
+    "into",
+    "just",
+    "more",
+    "most",
+    "other",
+    "over",
+    "should",
+    "some",
+    "such",
+    "than",
+    "that",
+    "their",
+    "them",
+    "then",
+    "there",
+    "these",
+    "they",
+    "this",
+    "those",
+    "through",
+    "under",
+    "until",
+    "very",
+    "what",
+    "when",
+    "where",
+    "which",
+    "while",
+    "will",
+    "with",
+    "would",
+    "your",
+    "guide",
+    "introduction",
+    "part",
+];
+
+static TOPIC_TAGGER: OnceLock<Arc<dyn LlmProvider>> = OnceLock::new();
+
+/// Use `provider` to infer topic tags for captured articles.
+///
+/// Called once at startup; later calls are ignored.
+pub fn set_topic_tagger(provider: Arc<dyn LlmProvider>) {
+    let _ = TOPIC_TAGGER.set(provider);
+}
+
+/// The page a browser handed over for capture.
+#[derive(Debug, Clone)]
+pub struct CapturedPage {
+    pub url: String,
+    pub title: String,
+    pub html: String,
+}
+
+/// One captured article, as listed in the capture index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureRecord {
+    /// Normalised URL (no fragment or tracking parameters).
+    pub url: String,
+    pub title: String,
+    /// Markdown file, relative to the workspace.
+    pub path: String,
+    pub site: Option<String>,
+    pub author: Option<String>,
+    pub published: Option<String>,
+    pub tags: Vec<String>,
+    /// True when only metadata could be saved.
+    pub content_missing: bool,
+    pub captured_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Knowledge-graph ids: the article node first, then linked entities.
+    pub entity_ids: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CaptureIndex {
+    captures: Vec<CaptureRecord>,
+}
+
+/// Result of capturing a page.
+#[derive(Debug)]
+pub struct CaptureOutcome {
+    pub record: CaptureRecord,
+    /// Whether an earlier capture of the same URL was updated.
+    pub updated: bool,
+    /// Knowledge-graph nodes created by this capture.
+    pub created_entities: Vec<String>,
+    /// Existing knowledge-graph nodes the article was linked to.
+    pub linked_entities: Vec<String>,
+}
+
+impl CaptureOutcome {
+    /// Summary for the model: stored path, flags and entity ids.
+    pub fn summary(&self) -> String {
+        let record = &self.record;
+        let mut out = format!(
+            "{} '{}'\nSaved to: {}\nURL: {}\nTags: {}\n",
+            if self.updated { "Updated" } else { "Captured" },
+            record.title,
+            record.path,
+            record.url,
+            if record.tags.is_empty() {
+                "(none)".to_string()
+            } else {
+                record.tags.join(", ")
+            }
+        );
+        if record.content_missing {
+            out.push_str(
+                "Content: not extracted (paywalled or empty page); metadata only was saved\n",
+            );
+        }
+        out.push_str(&format!(
+            "Knowledge graph: article {}\n",
+            record.entity_ids.first().map(String::as_str).unwrap_or("")
+        ));
+        if !self.created_entities.is_empty() {
+            out.push_str(&format!(
+                "  created: {}\n",
+                self.created_entities.join(", ")
+            ));
+        }
+        if !self.linked_entities.is_empty() {
+            out.push_str(&format!(
+                "  linked existing: {}\n",
+                self.linked_entities.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+/// Stores captured articles and records them in the knowledge graph.
+pub struct ReaderCapture {
+    workspace: PathBuf,
+}
+
+impl ReaderCapture {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.workspace
+            .join(".rustant")
+            .join("research")
+            .join("captures.json")
+    }
+
+    fn load_index(&self) -> CaptureIndex {
+        std::fs::read_to_string(self.index_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_index(&self, index: &CaptureIndex) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(index)
+            .map_err(|e| format!("Failed to serialize capture index: {}", e))?;
+        write_atomic(&self.index_path(), &json)
+    }
+
+    /// Capture `page`, adding `extra_tags` to the inferred topic tags.
+    pub async fn capture(
+        &self,
+        page: CapturedPage,
+        extra_tags: &[String],
+    ) -> Result<CaptureOutcome, String> {
+        let url = normalize_url(&page.url);
+        if url.is_empty() || url == "about:blank" {
+            return Err("the current tab has no page to capture".to_string());
+        }
+        let base = Url::parse(&url).ok();
+        let article = extract_article(&page.html, base.as_ref());
+        let content_missing = article.markdown.trim().chars().count() < MIN_CONTENT_CHARS;
+
+        let title = Some(page.title.trim())
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .or(article.title.clone())
+            .unwrap_or_else(|| url.clone());
+        let site = article.site_name.clone().or_else(|| {
+            base.as_ref()
+                .and_then(|u| u.host_str())
+                .map(|h| h.trim_start_matches("www.").to_string())
+        });
+
+        let mut tags = Vec::new();
+        for tag in extra_tags {
+            push_tag(&mut tags, tag);
+        }
+        let inferred = match TOPIC_TAGGER.get() {
+            Some(provider) if !content_missing => {
+                llm_tags(provider.as_ref(), &title, &article.markdown)
+                    .await
+                    .unwrap_or_else(|| keyword_tags(&title))
+            }
+            _ => keyword_tags(&title),
+        };
+        for tag in &inferred {
+            push_tag(&mut tags, tag);
+        }
+
+        let mut index = self.load_index();
+        let now = Utc::now();
+        let key = url_key(&url);
+        let previous = index.captures.iter().position(|c| c.url == url);
+        let rel_path = match previous {
+            Some(i) => index.captures[i].path.clone(),
+            None => format!("{}/{}-{}.md", CAPTURES_DIR, slugify(&title), &key[..8]),
+        };
+        let captured_at = previous
+            .map(|i| index.captures[i].captured_at)
+            .unwrap_or(now);
+        // Never replace a good capture with an empty re-extraction.
+        let keep_content =
+            content_missing && previous.is_some_and(|i| !index.captures[i].content_missing);
+
+        let stored_missing = content_missing && !keep_content;
+
+        let description = first_paragraph(&article.markdown);
+        let mut metadata = HashMap::from([
+            ("url".to_string(), url.clone()),
+            ("path".to_string(), rel_path.clone()),
+            ("content_missing".to_string(), stored_missing.to_string()),
+        ]);
+        if let Some(published) = &article.published {
+            metadata.insert("published".to_string(), published.clone());
+        }
+        let article_id = format!("article-{}", &key[..12]);
+        let links = KnowledgeGraphTool::new(self.workspace.clone())
+            .record_article(&ArticleEntry {
+                id: &article_id,
+                title: &title,
+                description: &description,
+                author: article.byline.as_deref(),
+                site: site.as_deref(),
+                tags: &tags,
+                metadata,
+                content: &article.markdown,
+            })
+            .map_err(|e| e.to_string())?;
+
+        let mut entity_ids = vec![links.article_id.clone()];
+        entity_ids.extend(
+            links
+                .created
+                .iter()
+                .chain(&links.linked)
+                .filter(|id| **id != links.article_id)
+                .cloned(),
+        );
+        let record = CaptureRecord {
+            url,
+            title,
+            path: rel_path,
+            site,
+            author: article.byline.clone(),
+            published: article.published.clone(),
+            tags,
+            content_missing: stored_missing,
+            captured_at,
+            updated_at: now,
+            entity_ids,
+        };
+
+        if !keep_content {
+            let body = if content_missing {
+                format!(
+                    "_Content could not be extracted (paywalled or empty page); only metadata was saved._\n\n{}",
+                    article.markdown.trim()
+                )
+            } else {
+                article.markdown.trim().to_string()
+            };
+            write_atomic(
+                &self.workspace.join(&record.path),
+                &render_capture(&record, &body),
+            )?;
+        }
+
+        match previous {
+            Some(i) => index.captures[i] = record.clone(),
+            None => index.captures.push(record.clone()),
+        }
+        self.save_index(&index)?;
+        debug!(url = %record.url, path = %record.path, "Captured article");
+
+        Ok(CaptureOutcome {
+            record,
+            updated: previous.is_some(),
+            created_entities: links.created,
+            linked_entities: links.linked,
+        })
+    }
+
+    /// Captures tagged `tag` (case-insensitive; all when `None`), newest first.
+    pub fn list(&self, tag: Option<&str>, limit: usize) -> Vec<CaptureRecord> {
+        let mut captures: Vec<CaptureRecord> = self
+            .load_index()
+            .captures
+            .into_iter()
+            .filter(|c| tag.is_none_or(|t| c.tags.iter().any(|ct| ct.eq_ignore_ascii_case(t))))
+            .collect();
+        captures.sort_by_key(|c| std::cmp::Reverse(c.updated_at));
+        captures.truncate(limit);
+        captures
+    }
+
+    /// Run the `list_captures` action shared by the capture tools.
+    pub fn list_output(&self, args: &Value) -> ToolOutput {
+        let tag = args["tag"]
+            .as_str()
+            .map(str::trim)
+            .filter(|t| !t.is_empty());
+        let limit = args["limit"].as_u64().unwrap_or(20) as usize;
+        let captures = self.list(tag, limit);
+        if captures.is_empty() {
+            return ToolOutput::text(match tag {
+                Some(tag) => format!("No captured articles tagged '{}'.", tag),
+                None => "No captured articles yet.".to_string(),
+            });
+        }
+        let mut out = format!("{} captured article(s):\n\n", captures.len());
+        for (i, c) in captures.iter().enumerate() {
+            out.push_str(&format!(
+                "{}. {}{}\n   {}\n   File: {}\n   Tags: {} | Captured: {}\n",
+                i + 1,
+                c.title,
+                if c.content_missing {
+                    " [metadata only]"
+                } else {
+                    ""
+                },
+                c.url,
+                c.path,
+                c.tags.join(", "),
+                c.captured_at.format("%Y-%m-%d"),
+            ));
+        }
+        ToolOutput::text(out)
+    }
+}
+
+/// Tags passed by the caller in the `tags` argument.
+pub(crate) fn tags_arg(args: &Value) -> Vec<String> {
+    args["tags"]
+        .as_array()
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|t| t.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Drop the fragment and `utm_*` tracking parameters so one article has one key.
+pub(crate) fn normalize_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url.trim()) else {
+        return url.trim().to_string();
+    };
+    parsed.set_fragment(None);
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| !k.starts_with("utm_"))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    parsed.to_string()
+}
+
+pub(crate) fn url_key(url: &str) -> String {
+    let digest = Sha256::digest(url.as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn slugify(title: &str) -> String {
+    let slug: String = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug: Vec<&str> = slug.split('-').filter(|s| !s.is_empty()).collect();
+    let slug: String = slug.join("-").chars().take(60).collect();
+    if slug.is_empty() {
+        "article".to_string()
+    } else {
+        slug.trim_end_matches('-').to_string()
+    }
+}
+
+fn push_tag(tags: &mut Vec<String>, tag: &str) {
+    let tag = tag
+        .trim()
+        .trim_start_matches(['#', '-', '*'])
+        .trim_matches(['"', '\'', '.'])
+        .trim()
+        .to_lowercase();
+    if (2..=40).contains(&tag.chars().count()) && !tags.contains(&tag) && tags.len() < MAX_TAGS {
+        tags.push(tag);
+    }
+}
+
+/// Fallback tags: distinctive words from the title.
+fn keyword_tags(title: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    for word in title.split(|c: char| !c.is_alphanumeric() && c != '-') {
+        let word = word.trim_matches('-').to_lowercase();
+        if word.chars().count() >= 4
+            && !word.chars().all(|c| c.is_ascii_digit())
+            && !STOPWORDS.contains(&word.as_str())
+            && !tags.contains(&word)
+        {
+            tags.push(word);
+        }
+        if tags.len() == 3 {
+            break;
+        }
+    }
+    tags
+}
+
+/// Ask the tagger model for a handful of topic tags.
+async fn llm_tags(provider: &dyn LlmProvider, title: &str, markdown: &str) -> Option<Vec<String>> {
+    let excerpt: String = markdown.chars().take(TAGGER_EXCERPT_CHARS).collect();
+    let request = CompletionRequest {
+        messages: vec![
+            Message::system("You label articles with topic tags."),
+            Message::user(format!(
+                "Give 3 to 5 short topic tags for this article, lowercase, comma-separated, \
+                 with nothing else.\n\nTitle: {}\n\n{}",
+                title, excerpt
+            )),
+        ],
+        temperature: 0.0,
+        max_tokens: Some(40),
+        ..Default::default()
+    };
+    let response = match tokio::time::timeout(TAGGER_TIMEOUT, provider.complete(request)).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => {
+            warn!("Topic tagging failed: {}", e);
+            return None;
+        }
+        Err(_) => {
+            warn!("Topic tagging timed out");
+            return None;
+        }
+    };
+    let text = response.message.content.as_text()?;
+    let mut tags = Vec::new();
+    for tag in text.split([',', '\n']) {
+        push_tag(&mut tags, tag);
+    }
+    (!tags.is_empty()).then_some(tags)
+}
+
+/// The first prose paragraph, shortened for the graph node description.
+fn first_paragraph(markdown: &str) -> String {
+    let paragraph = markdown
+        .split("\n\n")
+        .map(str::trim)
+        .find(|p| !p.is_empty() && !p.starts_with('#') && !p.starts_with('!'))
+        .unwrap_or("");
+    let mut text: String = paragraph.chars().take(300).collect();
+    if paragraph.chars().count() > 300 {
+        text.push('…');
+    }
+    text
+}
+
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn render_capture(record: &CaptureRecord, body: &str) -> String {
+    let mut out = String::from("---\n");
+    out.push_str(&format!("title: {}\n", yaml_string(&record.title)));
+    out.push_str(&format!("url: {}\n", yaml_string(&record.url)));
+    for (key, value) in [
+        ("site", &record.site),
+        ("author", &record.author),
+        ("published", &record.published),
+    ] {
+        if let Some(value) = value {
+            out.push_str(&format!("{}: {}\n", key, yaml_string(value)));
+        }
+    }
+    let tags: Vec<String> = record.tags.iter().map(|t| yaml_string(t)).collect();
+    out.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+    out.push_str(&format!(
+        "captured_at: {}\n",
+        record.captured_at.to_rfc3339()
+    ));
+    out.push_str(&format!("updated_at: {}\n", record.updated_at.to_rfc3339()));
+    out.push_str(&format!("content_missing: {}\n", record.content_missing));
+    out.push_str(&format!("entities: [{}]\n", record.entity_ids.join(", ")));
+    out.push_str("---\n\n");
+    out.push_str(&format!("# {}\n\n", record.title));
+    out.push_str(body);
+    out.push('\n');
+    out
+}
+
+fn write_atomic(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Cross-platform capture of the active tab in the CDP browser session.
+pub struct BrowserCaptureTool {
+    ctx: BrowserToolContext,
+    capture: ReaderCapture,
+}
+
+impl BrowserCaptureTool {
+    pub fn new(ctx: BrowserToolContext, workspace: PathBuf) -> Self {
+        Self {
+            ctx,
+            capture: ReaderCapture::new(workspace),
+        }
+    }
+
+    async fn current_page(&self) -> Result<CapturedPage, ToolError> {
+        let failed = |e: rustant_core::error::BrowserError| ToolError::ExecutionFailed {
+            name: "browser_capture".into(),
+            message: e.to_string(),
+        };
+        Ok(CapturedPage {
+            url: self.ctx.client.get_url().await.map_err(failed)?,
+            title: self.ctx.client.get_title().await.unwrap_or_default(),
+            html: self.ctx.client.get_html().await.map_err(failed)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for BrowserCaptureTool {
+    fn name(&self) -> &str {
+        "browser_capture"
+    }
+
+    fn description(&self) -> &str {
+        "Save the current browser tab for later reasoning. Actions: capture (store the page's \
+         reader-mode content as markdown under .rustant/research/captures and add the article, \
+         author, site and topics to the knowledge graph; capturing a URL again updates it), \
+         list_captures (list saved articles, optionally by tag)."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["capture", "list_captures"],
+                    "description": "Action to perform"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Extra topic tags for capture"
+                },
+                "tag": { "type": "string", "description": "Only list captures with this tag" },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum captures to list (default 20)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolOutput, ToolError> {
+        match args["action"].as_str().unwrap_or("") {
+            "capture" => {
+                let page = self.current_page().await?;
+                self.ctx.security.check_url(&page.url).map_err(|reason| {
+                    ToolError::PermissionDenied {
+                        name: "browser_capture".into(),
+                        reason,
+                    }
+                })?;
+                let outcome =
+                    self.capture
+                        .capture(page, &tags_arg(&args))
+                        .await
+                        .map_err(|message| ToolError::ExecutionFailed {
+                            name: "browser_capture".into(),
+                            message,
+                        })?;
+                Ok(ToolOutput::text(outcome.summary()))
+            }
+            "list_captures" => Ok(self.capture.list_output(&args)),
+            other => Err(ToolError::InvalidArguments {
+                name: "browser_capture".into(),
+                reason: format!("unknown action '{}'. Valid: capture, list_captures", other),
+            }),
+        }
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Write
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustant_core::browser::{BrowserSecurityGuard, MockCdpClient};
+    use tempfile::TempDir;
+
+    fn article_html(body: &str) -> String {
+        format!(
+            r#"<html><head><title>Rust Async Runtimes Explained</title>
+            <meta property="og:site_name" content="Example Blog">
+            <meta name="author" content="Jane Doe"></head>
+            <body><nav>Home | About</nav><article>{}</article></body></html>"#,
+            body
+        )
+    }
+
+    fn long_body() -> String {
+        "<p>Tokio schedules async tasks on a work-stealing runtime, and this article \
+         compares it with other executors, covering wakers, reactors and the cost of \
+         cooperative scheduling in detail for production services.</p>"
+            .repeat(3)
+    }
+
+    fn page(url: &str, html: String) -> CapturedPage {
+        CapturedPage {
+            url: url.to_string(),
+            title: "Rust Async Runtimes Explained".to_string(),
+            html,
+        }
+    }
+
+    #[test]
+    fn test_normalize_url_drops_fragment_and_tracking() {
+        assert_eq!(
+            normalize_url("https://Example.com/post?id=3&utm_source=x#comments"),
+            "https://example.com/post?id=3"
+        );
+        assert_eq!(
+            normalize_url("https://example.com/post?utm_medium=mail"),
+            "https://example.com/post"
+        );
+    }
+
+    #[test]
+    fn test_keyword_tags_and_slug() {
+        assert_eq!(
+            keyword_tags("A Guide to Rust Async Runtimes in 2024"),
+            vec!["rust", "async", "runtimes"]
+        );
+        assert_eq!(
+            slugify("Rust: Async/Await, Explained!"),
+            "rust-async-await-explained"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_capture_stores_markdown_and_graph_entities() {
+        let dir = TempDir::new().unwrap();
+        let graph = KnowledgeGraphTool::new(dir.path().to_path_buf());
+        graph
+            .execute(json!({"action": "add_node", "name": "Tokio", "node_type": "method"}))
+            .await
+            .unwrap();
+        let capture = ReaderCapture::new(dir.path().to_path_buf());
+
+        let outcome = capture
+            .capture(
+                page("https://example.com/async#top", article_html(&long_body())),
+                &["Concurrency".to_string()],
+            )
+            .await
+            .unwrap();
+        let record = &outcome.record;
+        assert!(!outcome.updated);
+        assert!(!record.content_missing);
+        assert_eq!(record.url, "https://example.com/async");
+        assert_eq!(
+            record.tags,
+            vec!["concurrency", "rust", "async", "runtimes"]
+        );
+        assert_eq!(record.author.as_deref(), Some("Jane Doe"));
+        assert!(
+            record
+                .path
+                .starts_with(".rustant/research/captures/rust-async-runtimes-explained-")
+        );
+
+        let file = std::fs::read_to_string(dir.path().join(&record.path)).unwrap();
+        assert!(file.starts_with("---\ntitle: \"Rust Async Runtimes Explained\"\n"));
+        assert!(file.contains("content_missing: false"));
+        assert!(file.contains("work-stealing runtime"));
+        assert!(!file.contains("Home | About"));
+
+        // Article, author and site are new; "Tokio" existed and is mentioned.
+        assert!(outcome.created_entities.contains(&"jane-doe".to_string()));
+        assert!(
+            outcome
+                .created_entities
+                .contains(&"example-blog".to_string())
+        );
+        assert_eq!(outcome.linked_entities, vec!["tokio".to_string()]);
+        assert_eq!(record.entity_ids[0], outcome.created_entities[0]);
+        assert!(outcome.summary().contains(&record.path));
+    }
+
+    #[tokio::test]
+    async fn test_recapture_updates_and_keeps_content() {
+        let dir = TempDir::new().unwrap();
+        let capture = ReaderCapture::new(dir.path().to_path_buf());
+        let first = capture
+            .capture(
+                page("https://example.com/async", article_html(&long_body())),
+                &[],
+            )
+            .await
+            .unwrap();
+
+        // Same URL behind a paywall now: update, don't duplicate or clobber.
+        let second = capture
+            .capture(
+                page(
+                    "https://example.com/async?utm_campaign=feed",
+                    article_html("<p>Subscribe to read.</p>"),
+                ),
+                &[],
+            )
+            .await
+            .unwrap();
+        assert!(second.updated);
+        assert_eq!(second.record.path, first.record.path);
+        assert!(!second.record.content_missing);
+        assert_eq!(capture.list(None, 10).len(), 1);
+        let file = std::fs::read_to_string(dir.path().join(&first.record.path)).unwrap();
+        assert!(file.contains("work-stealing runtime"));
+        assert!(second.created_entities.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_paywalled_page_saves_metadata_and_lists_by_tag() {
+        let dir = TempDir::new().unwrap();
+        let capture = ReaderCapture::new(dir.path().to_path_buf());
+        let outcome = capture
+            .capture(
+                page(
+                    "https://news.example.com/story",
+                    article_html("<p>Subscribe to read.</p>"),
+                ),
+                &["markets".to_string()],
+            )
+            .await
+            .unwrap();
+        assert!(outcome.record.content_missing);
+        assert!(outcome.summary().contains("metadata only was saved"));
+        let file = std::fs::read_to_string(dir.path().join(&outcome.record.path)).unwrap();
+        assert!(file.contains("content_missing: true"));
+
+        assert_eq!(capture.list(Some("MARKETS"), 10).len(), 1);
+        assert!(capture.list(Some("sports"), 10).is_empty());
+        let listed = capture.list_output(&json!({"tag": "markets"})).content;
+        assert!(listed.contains("[metadata only]"));
+        assert!(listed.contains("https://news.example.com/story"));
+    }
+
+    #[tokio::test]
+    async fn test_browser_capture_reads_active_tab() {
+        let dir = TempDir::new().unwrap();
+        let client = Arc::new(MockCdpClient::new());
+        client.set_url("https://example.com/async");
+        client.set_title("Rust Async Runtimes Explained");
+        client.set_html(article_html(&long_body()));
+        let ctx = BrowserToolContext::new(client, Arc::new(BrowserSecurityGuard::default()));
+        let tool = BrowserCaptureTool::new(ctx, dir.path().to_path_buf());
+
+        let output = tool.execute(json!({"action": "capture"})).await.unwrap();
+        assert!(
+            output
+                .content
+                .contains("Captured 'Rust Async Runtimes Explained'")
+        );
+        let listed = tool
+            .execute(json!({"action": "list_captures"}))
+            .await
+            .unwrap();
+        assert!(listed.content.contains("1 captured article(s)"));
+    }
+}
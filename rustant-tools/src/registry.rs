@@ -6,6 +6,7 @@
 
 use async_trait::async_trait;
 use rustant_core::error::ToolError;
+use rustant_core::tool_aliases::{AliasStage, AliasTable, ToolAlias};
 use rustant_core::types::{RiskLevel, ToolDefinition, ToolOutput};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -34,18 +35,56 @@ pub trait Tool: Send + Sync {
     fn timeout(&self) -> Duration {
         Duration::from_secs(30)
     }
+
+    /// Old names this tool can still be called by. Built-in tools get
+    /// their renames from [`rustant_core::tool_aliases`].
+    fn aliases(&self) -> Vec<ToolAlias> {
+        rustant_core::tool_aliases::builtin_aliases(self.name())
+    }
+
+    /// One-line summary of a call's arguments for progress output, e.g.
+    /// `src/main.rs L1-40`. The agent adds the tool name, makes paths
+    /// workspace-relative and redacts secrets.
+    fn summarize_args(&self, args: &serde_json::Value) -> String {
+        rustant_core::tool_call::summarize_args(args)
+    }
 }
 
 /// The tool registry holds all registered tools and handles execution.
 #[derive(Clone)]
 pub struct ToolRegistry {
     tools: HashMap<String, Arc<dyn Tool>>,
+    aliases: AliasTable,
+    /// Whether [`list_definitions`](Self::list_definitions) includes
+    /// deprecated aliases next to their tools.
+    list_aliases: bool,
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            aliases: AliasTable::new(rustant_core::tool_aliases::VERSION),
+            list_aliases: false,
+        }
+    }
+
+    /// List deprecated aliases next to their tools (`[tools] list_aliases`).
+    pub fn set_list_aliases(&mut self, list_aliases: bool) {
+        self.list_aliases = list_aliases;
+    }
+
+    /// Aliases of the registered tools.
+    pub fn aliases(&self) -> &AliasTable {
+        &self.aliases
+    }
+
+    /// Resolve `name` to a registered tool's name without logging. Removed
+    /// aliases resolve to nothing.
+    fn canonical<'a>(&'a self, name: &'a str) -> &'a str {
+        match self.aliases.get(name) {
+            Some(alias) if self.aliases.stage(alias) != AliasStage::Removed => &alias.canonical,
+            _ => name,
         }
     }
 
@@ -56,6 +95,9 @@ impl ToolRegistry {
             return Err(ToolError::AlreadyRegistered { name });
         }
         debug!(tool = %name, "Registering tool");
+        for alias in tool.aliases() {
+            self.aliases.insert(alias);
+        }
         self.tools.insert(name, tool);
         Ok(())
     }
@@ -71,21 +113,39 @@ impl ToolRegistry {
         Ok(())
     }
 
-    /// Get a tool by name.
+    /// Get a tool by name or alias.
     pub fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
-        self.tools.get(name).cloned()
+        self.tools.get(self.canonical(name)).cloned()
     }
 
-    /// List all registered tool definitions (for sending to LLM).
+    /// List all registered tool definitions (for sending to LLM). Aliases in
+    /// their deprecated stage are included when alias listing is on.
     pub fn list_definitions(&self) -> Vec<ToolDefinition> {
-        self.tools
+        let mut definitions: Vec<ToolDefinition> = self
+            .tools
             .values()
             .map(|tool| ToolDefinition {
                 name: tool.name().to_string(),
                 description: tool.description().to_string(),
                 parameters: tool.parameters_schema(),
             })
-            .collect()
+            .collect();
+        if self.list_aliases {
+            for alias in self.aliases.iter() {
+                let Some(tool) = self.tools.get(&alias.canonical) else {
+                    continue;
+                };
+                if self.aliases.stage(alias) != AliasStage::Deprecated {
+                    continue;
+                }
+                definitions.push(ToolDefinition {
+                    name: alias.alias.clone(),
+                    description: format!("Deprecated: {}. {}", alias.notice(), tool.description()),
+                    parameters: tool.parameters_schema(),
+                });
+            }
+        }
+        definitions
     }
 
     /// List all registered tool names.
@@ -95,12 +155,14 @@ impl ToolRegistry {
 
     /// Get the risk level of a tool by name.
     pub fn get_risk_level(&self, name: &str) -> Option<RiskLevel> {
-        self.tools.get(name).map(|t| t.risk_level())
+        self.tools.get(self.canonical(name)).map(|t| t.risk_level())
     }
 
     /// Get the parameters schema for a tool by name.
     pub fn get_parameters_schema(&self, name: &str) -> Option<serde_json::Value> {
-        self.tools.get(name).map(|t| t.parameters_schema())
+        self.tools
+            .get(self.canonical(name))
+            .map(|t| t.parameters_schema())
     }
 
     /// Get the number of registered tools.
@@ -113,12 +175,14 @@ impl ToolRegistry {
         self.tools.is_empty()
     }
 
-    /// Execute a tool by name with the given arguments, applying timeout.
+    /// Execute a tool by name or alias with the given arguments, applying
+    /// timeout.
     pub async fn execute(
         &self,
         name: &str,
         args: serde_json::Value,
     ) -> Result<ToolOutput, ToolError> {
+        let name = self.aliases.resolve(name)?;
         let tool = self.tools.get(name).ok_or_else(|| ToolError::NotFound {
             name: name.to_string(),
         })?;
@@ -330,6 +394,84 @@ mod tests {
         }
     }
 
+    /// Echo tool registered under a new name with "echo" as its old one.
+    struct RenamedEchoTool(ToolAlias);
+
+    #[async_trait]
+    impl Tool for RenamedEchoTool {
+        fn name(&self) -> &str {
+            "say"
+        }
+
+        fn description(&self) -> &str {
+            EchoTool.description()
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            EchoTool.parameters_schema()
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> Result<ToolOutput, ToolError> {
+            EchoTool.execute(args).await
+        }
+
+        fn risk_level(&self) -> RiskLevel {
+            RiskLevel::ReadOnly
+        }
+
+        fn aliases(&self) -> Vec<ToolAlias> {
+            vec![self.0.clone()]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_alias_resolves_to_renamed_tool() {
+        let mut registry = ToolRegistry::new();
+        registry
+            .register(Arc::new(RenamedEchoTool(ToolAlias::new(
+                "echo", "say", "1.0.0",
+            ))))
+            .unwrap();
+
+        assert_eq!(registry.get("echo").unwrap().name(), "say");
+        assert_eq!(registry.get_risk_level("echo"), Some(RiskLevel::ReadOnly));
+        let result = registry
+            .execute("echo", serde_json::json!({"text": "hi"}))
+            .await
+            .unwrap();
+        assert_eq!(result.content, "Echo: hi");
+
+        let names = |r: &ToolRegistry| {
+            let mut names: Vec<String> = r.list_definitions().into_iter().map(|d| d.name).collect();
+            names.sort();
+            names
+        };
+        assert_eq!(names(&registry), vec!["say"]);
+        registry.set_list_aliases(true);
+        assert_eq!(names(&registry), vec!["echo", "say"]);
+    }
+
+    #[tokio::test]
+    async fn test_removed_alias_names_replacement() {
+        let mut registry = ToolRegistry::new();
+        let alias = ToolAlias::new("echo", "say", "0.1.0").removed_in("0.2.0");
+        registry.register(Arc::new(RenamedEchoTool(alias))).unwrap();
+        registry.set_list_aliases(true);
+
+        assert!(registry.get("echo").is_none());
+        assert_eq!(registry.list_definitions().len(), 1);
+        match registry
+            .execute("echo", serde_json::json!({"text": "hi"}))
+            .await
+        {
+            Err(ToolError::Removed { replacement, .. }) => assert_eq!(replacement, "say"),
+            other => panic!(
+                "Expected Removed error, got: {:?}",
+                other.map(|o| o.content)
+            ),
+        }
+    }
+
     #[test]
     fn test_get_nonexistent() {
         let registry = ToolRegistry::new();
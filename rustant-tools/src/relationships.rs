@@ -1,42 +1,54 @@
-//! Relationships tool — track contacts and interaction history.
+//! Relationships tool — contacts, interaction timelines and reminders, backed by
+//! the workspace person graph.
+//!
+//! Contacts added here are merged with the people seen on channels and in
+//! Contacts.app (see [`rustant_core::people`]), so a timeline covers every
+//! channel a person uses. Merge and split correct wrong auto-matches, and
+//! forget/export act on one person's data.
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use rustant_core::error::ToolError;
+use rustant_core::people::{
+    InteractionDirection, PeopleError, Person, PersonGraph, PersonInteraction, PersonObservation,
+    SuggestionConfig,
+};
 use rustant_core::types::{RiskLevel, ToolOutput};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use serde_json::{Value, json};
 use std::path::PathBuf;
 
 use crate::registry::Tool;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Contact {
-    id: usize,
+const TOOL_NAME: &str = "relationships";
+
+/// Contact as stored by earlier versions in `relationships/contacts.json`.
+#[derive(Debug, Deserialize)]
+struct LegacyContact {
     name: String,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
     email: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
     phone: Option<String>,
     #[serde(default)]
     notes: String,
     #[serde(default)]
     tags: Vec<String>,
-    interactions: Vec<Interaction>,
-    created_at: DateTime<Utc>,
+    #[serde(default)]
+    interactions: Vec<LegacyInteraction>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Interaction {
+#[derive(Debug, Deserialize)]
+struct LegacyInteraction {
     date: DateTime<Utc>,
-    kind: String, // "call", "email", "meeting", "message", etc.
+    kind: String,
     note: String,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
-struct RelationshipsState {
-    contacts: Vec<Contact>,
-    next_id: usize,
+#[derive(Debug, Default, Deserialize)]
+struct LegacyState {
+    #[serde(default)]
+    contacts: Vec<LegacyContact>,
 }
 
 pub struct RelationshipsTool {
@@ -48,60 +60,163 @@ impl RelationshipsTool {
         Self { workspace }
     }
 
-    fn state_path(&self) -> PathBuf {
+    fn legacy_path(&self) -> PathBuf {
         self.workspace
             .join(".rustant")
             .join("relationships")
             .join("contacts.json")
     }
 
-    fn load_state(&self) -> RelationshipsState {
-        let path = self.state_path();
-        if path.exists() {
-            std::fs::read_to_string(&path)
-                .ok()
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or_default()
-        } else {
-            RelationshipsState {
-                contacts: Vec::new(),
-                next_id: 1,
+    /// Load the person graph, importing contacts from the old standalone
+    /// store the first time.
+    fn load_graph(&self) -> Result<PersonGraph, ToolError> {
+        let mut graph = PersonGraph::load(&self.workspace);
+        let legacy = self.legacy_path();
+        let Some(state) = std::fs::read_to_string(&legacy)
+            .ok()
+            .and_then(|s| serde_json::from_str::<LegacyState>(&s).ok())
+        else {
+            return Ok(graph);
+        };
+        for contact in state.contacts {
+            let mut obs = PersonObservation::from_handle("relationships", &contact.name)
+                .with_name(&contact.name);
+            obs.emails.extend(contact.email);
+            obs.phones.extend(contact.phone);
+            let id = graph.observe(obs);
+            if let Some(person) = graph.get_mut(id) {
+                if person.notes.is_empty() {
+                    person.notes = contact.notes;
+                }
+                for tag in contact.tags {
+                    if !person.tags.contains(&tag) {
+                        person.tags.push(tag);
+                    }
+                }
+            }
+            for interaction in contact.interactions {
+                let _ = graph.record_interaction(
+                    id,
+                    logged(interaction.date, &interaction.kind, &interaction.note),
+                );
             }
         }
+        graph.save(&self.workspace).map_err(failed)?;
+        std::fs::rename(&legacy, legacy.with_extension("json.migrated"))
+            .map_err(|e| failed(format!("Failed to retire {}: {}", legacy.display(), e)))?;
+        Ok(graph)
+    }
+
+    fn save_graph(&self, graph: &PersonGraph) -> Result<(), ToolError> {
+        graph.save(&self.workspace).map_err(failed)
+    }
+}
+
+/// An interaction the user logged by hand.
+fn logged(at: DateTime<Utc>, kind: &str, note: &str) -> PersonInteraction {
+    PersonInteraction {
+        at,
+        channel: kind.to_string(),
+        direction: InteractionDirection::Outgoing,
+        summary: note.to_string(),
+        topic: None,
+        needs_reply: false,
+        handle: None,
     }
+}
 
-    fn save_state(&self, state: &RelationshipsState) -> Result<(), ToolError> {
-        let path = self.state_path();
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| ToolError::ExecutionFailed {
-                name: "relationships".to_string(),
-                message: format!("Failed to create dir: {}", e),
-            })?;
+fn failed(e: impl std::fmt::Display) -> ToolError {
+    ToolError::ExecutionFailed {
+        name: TOOL_NAME.to_string(),
+        message: e.to_string(),
+    }
+}
+
+fn arg_id(args: &Value, key: &str) -> usize {
+    args.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as usize
+}
+
+fn summary_line(person: &Person) -> String {
+    let last = person
+        .last_contacted()
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "never".to_string());
+    let mut line = format!(
+        "  #{} — {} (last contact: {})",
+        person.id, person.name, last
+    );
+    let channels = person.channels_used();
+    if !channels.is_empty() {
+        line.push_str(&format!(" via {}", channels.join(", ")));
+    }
+    line
+}
+
+fn timeline(person: &Person, limit: usize) -> String {
+    let mut out = format!("#{} — {}\n", person.id, person.name);
+    let identifiers: Vec<String> = person
+        .handles
+        .iter()
+        .map(|h| h.to_string())
+        .chain(person.emails.iter().cloned())
+        .chain(person.phones.iter().cloned())
+        .collect();
+    if !identifiers.is_empty() {
+        out.push_str(&format!("Known as: {}\n", identifiers.join(", ")));
+    }
+    if !person.notes.is_empty() {
+        out.push_str(&format!("Notes: {}\n", person.notes));
+    }
+    match person.last_contacted() {
+        Some(last) => out.push_str(&format!(
+            "Last contact: {}\n",
+            last.format("%Y-%m-%d %H:%M")
+        )),
+        None => out.push_str("Last contact: never\n"),
+    }
+    let channels = person.channels_used();
+    if !channels.is_empty() {
+        out.push_str(&format!("Channels: {}\n", channels.join(", ")));
+    }
+    let topics = person.topics();
+    if !topics.is_empty() {
+        out.push_str(&format!("Topics: {}\n", topics.join(", ")));
+    }
+    if let Some(pending) = person.awaiting_reply() {
+        out.push_str(&format!(
+            "Awaiting your reply since {}: {}\n",
+            pending.at.format("%Y-%m-%d"),
+            pending.summary
+        ));
+    }
+    if !person.interactions.is_empty() {
+        out.push_str("Recent interactions:\n");
+        for i in person.interactions.iter().rev().take(limit) {
+            let arrow = match i.direction {
+                InteractionDirection::Incoming => "←",
+                InteractionDirection::Outgoing => "→",
+            };
+            out.push_str(&format!(
+                "  {} {} {} {}\n",
+                i.at.format("%Y-%m-%d"),
+                arrow,
+                i.channel,
+                i.summary
+            ));
         }
-        let json = serde_json::to_string_pretty(state).map_err(|e| ToolError::ExecutionFailed {
-            name: "relationships".to_string(),
-            message: format!("Serialize error: {}", e),
-        })?;
-        let tmp = path.with_extension("json.tmp");
-        std::fs::write(&tmp, &json).map_err(|e| ToolError::ExecutionFailed {
-            name: "relationships".to_string(),
-            message: format!("Write error: {}", e),
-        })?;
-        std::fs::rename(&tmp, &path).map_err(|e| ToolError::ExecutionFailed {
-            name: "relationships".to_string(),
-            message: format!("Rename error: {}", e),
-        })?;
-        Ok(())
     }
+    out
 }
 
 #[async_trait]
 impl Tool for RelationshipsTool {
     fn name(&self) -> &str {
-        "relationships"
+        TOOL_NAME
     }
     fn description(&self) -> &str {
-        "Track contacts and interaction history. Actions: add_contact, update, search, list, log_interaction."
+        "Track people across contacts and channels, with interaction timelines and reminders \
+         to get back in touch. Actions: add_contact, update, search, list, log_interaction, \
+         timeline, suggestions, merge, split, forget, export."
     }
     fn parameters_schema(&self) -> Value {
         json!({
@@ -109,17 +224,29 @@ impl Tool for RelationshipsTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["add_contact", "update", "search", "list", "log_interaction"],
+                    "enum": [
+                        "add_contact", "update", "search", "list", "log_interaction",
+                        "timeline", "suggestions", "merge", "split", "forget", "export"
+                    ],
                     "description": "Action to perform"
                 },
                 "name": { "type": "string", "description": "Contact name" },
                 "email": { "type": "string", "description": "Email address" },
                 "phone": { "type": "string", "description": "Phone number" },
                 "notes": { "type": "string", "description": "Notes about the contact" },
-                "id": { "type": "integer", "description": "Contact ID" },
+                "id": { "type": "integer", "description": "Person ID" },
+                "other_id": { "type": "integer", "description": "Person ID to merge into 'id'" },
+                "identifiers": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Handles, emails or phone numbers to split off into a new person"
+                },
                 "kind": { "type": "string", "description": "Interaction type (call, email, meeting, message)" },
                 "note": { "type": "string", "description": "Interaction note" },
-                "query": { "type": "string", "description": "Search query" }
+                "query": { "type": "string", "description": "Search query" },
+                "limit": { "type": "integer", "description": "Maximum entries to show" },
+                "reply_after_days": { "type": "integer", "description": "Days before an unanswered question is suggested (default 3)" },
+                "stale_after_days": { "type": "integer", "description": "Days without contact before a regular contact is suggested (default 30)" }
             },
             "required": ["action"]
         })
@@ -130,7 +257,7 @@ impl Tool for RelationshipsTool {
 
     async fn execute(&self, args: Value) -> Result<ToolOutput, ToolError> {
         let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("");
-        let mut state = self.load_state();
+        let mut graph = self.load_graph()?;
 
         match action {
             "add_contact" => {
@@ -142,53 +269,61 @@ impl Tool for RelationshipsTool {
                 if name.is_empty() {
                     return Ok(ToolOutput::text("Please provide a contact name."));
                 }
-                let id = state.next_id;
-                state.next_id += 1;
-                state.contacts.push(Contact {
-                    id,
-                    name: name.to_string(),
-                    email: args
-                        .get("email")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string()),
-                    phone: args
-                        .get("phone")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string()),
-                    notes: args
-                        .get("notes")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string(),
-                    tags: Vec::new(),
-                    interactions: Vec::new(),
-                    created_at: Utc::now(),
-                });
-                self.save_state(&state)?;
-                Ok(ToolOutput::text(format!(
-                    "Added contact '{}' (#{}).",
-                    name, id
-                )))
+                let mut obs = PersonObservation::from_handle("relationships", name).with_name(name);
+                if let Some(email) = args.get("email").and_then(|v| v.as_str()) {
+                    obs = obs.with_email(email);
+                }
+                if let Some(phone) = args.get("phone").and_then(|v| v.as_str()) {
+                    obs = obs.with_phone(phone);
+                }
+                let known = graph.people.len();
+                let id = graph.observe(obs);
+                if let Some(notes) = args.get("notes").and_then(|v| v.as_str())
+                    && let Some(person) = graph.get_mut(id)
+                {
+                    person.notes = notes.to_string();
+                }
+                self.save_graph(&graph)?;
+                if graph.people.len() > known {
+                    Ok(ToolOutput::text(format!(
+                        "Added contact '{}' (#{}).",
+                        name, id
+                    )))
+                } else {
+                    let existing = graph.get(id).map(|p| p.name.as_str()).unwrap_or(name);
+                    Ok(ToolOutput::text(format!(
+                        "Linked '{}' to existing person '{}' (#{}).",
+                        name, existing, id
+                    )))
+                }
             }
             "update" => {
-                let id = args.get("id").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
-                if let Some(contact) = state.contacts.iter_mut().find(|c| c.id == id) {
-                    if let Some(name) = args.get("name").and_then(|v| v.as_str()) {
-                        contact.name = name.to_string();
-                    }
-                    if let Some(email) = args.get("email").and_then(|v| v.as_str()) {
-                        contact.email = Some(email.to_string());
-                    }
-                    if let Some(phone) = args.get("phone").and_then(|v| v.as_str()) {
-                        contact.phone = Some(phone.to_string());
-                    }
-                    if let Some(notes) = args.get("notes").and_then(|v| v.as_str()) {
-                        contact.notes = notes.to_string();
-                    }
-                    self.save_state(&state)?;
-                    Ok(ToolOutput::text(format!("Updated contact #{}.", id)))
-                } else {
-                    Ok(ToolOutput::text(format!("Contact #{} not found.", id)))
+                let id = arg_id(&args, "id");
+                let Some(person) = graph.get_mut(id) else {
+                    return Ok(ToolOutput::text(format!("Contact #{} not found.", id)));
+                };
+                if let Some(name) = args.get("name").and_then(|v| v.as_str()) {
+                    person.name = name.to_string();
+                }
+                if let Some(notes) = args.get("notes").and_then(|v| v.as_str()) {
+                    person.notes = notes.to_string();
+                }
+                let mut obs = PersonObservation::default();
+                if let Some(email) = args.get("email").and_then(|v| v.as_str()) {
+                    obs = obs.with_email(email);
+                }
+                if let Some(phone) = args.get("phone").and_then(|v| v.as_str()) {
+                    obs = obs.with_phone(phone);
+                }
+                let owners = graph.link(id, obs).map_err(failed)?;
+                self.save_graph(&graph)?;
+                match owners.first() {
+                    Some(owner) => Ok(ToolOutput::text(format!(
+                        "Updated contact #{}, but that email or phone already belongs to #{}. \
+                         Use merge to combine them.",
+                        id, owner
+                    ))),
+                    None => Ok(ToolOutput::text(format!("Updated contact #{}.", id))),
                 }
             }
             "search" => {
@@ -196,28 +331,18 @@ impl Tool for RelationshipsTool {
                     .get("query")
                     .and_then(|v| v.as_str())
                     .or_else(|| args.get("name").and_then(|v| v.as_str()))
-                    .unwrap_or("")
-                    .to_lowercase();
-                let matches: Vec<String> = state
-                    .contacts
-                    .iter()
-                    .filter(|c| {
-                        c.name.to_lowercase().contains(&query)
-                            || c.email
-                                .as_deref()
-                                .unwrap_or("")
-                                .to_lowercase()
-                                .contains(&query)
-                            || c.notes.to_lowercase().contains(&query)
-                    })
-                    .map(|c| {
-                        let email = c.email.as_deref().unwrap_or("N/A");
+                    .unwrap_or("");
+                let matches: Vec<String> = graph
+                    .find(query)
+                    .into_iter()
+                    .map(|p| {
+                        let email = p.emails.first().map(|e| e.as_str()).unwrap_or("N/A");
                         format!(
                             "  #{} — {} ({}) — {} interactions",
-                            c.id,
-                            c.name,
+                            p.id,
+                            p.name,
                             email,
-                            c.interactions.len()
+                            p.interactions.len()
                         )
                     })
                     .collect();
@@ -235,49 +360,141 @@ impl Tool for RelationshipsTool {
                 }
             }
             "list" => {
-                if state.contacts.is_empty() {
+                if graph.people.is_empty() {
                     return Ok(ToolOutput::text("No contacts yet."));
                 }
-                let lines: Vec<String> = state
-                    .contacts
-                    .iter()
-                    .map(|c| {
-                        let last = c
-                            .interactions
-                            .last()
-                            .map(|i| i.date.format("%Y-%m-%d").to_string())
-                            .unwrap_or_else(|| "never".to_string());
-                        format!("  #{} — {} (last contact: {})", c.id, c.name, last)
-                    })
-                    .collect();
+                let lines: Vec<String> = graph.people.iter().map(summary_line).collect();
                 Ok(ToolOutput::text(format!(
                     "Contacts ({}):\n{}",
-                    state.contacts.len(),
+                    graph.people.len(),
                     lines.join("\n")
                 )))
             }
             "log_interaction" => {
-                let id = args.get("id").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+                let id = arg_id(&args, "id");
                 let kind = args.get("kind").and_then(|v| v.as_str()).unwrap_or("note");
                 let note = args.get("note").and_then(|v| v.as_str()).unwrap_or("");
-                if let Some(contact) = state.contacts.iter_mut().find(|c| c.id == id) {
-                    let contact_name = contact.name.clone();
-                    contact.interactions.push(Interaction {
-                        date: Utc::now(),
-                        kind: kind.to_string(),
-                        note: note.to_string(),
-                    });
-                    self.save_state(&state)?;
-                    Ok(ToolOutput::text(format!(
-                        "Logged {} interaction for '{}'.",
-                        kind, contact_name
-                    )))
-                } else {
-                    Ok(ToolOutput::text(format!("Contact #{} not found.", id)))
+                match graph.record_interaction(id, logged(Utc::now(), kind, note)) {
+                    Ok(()) => {
+                        self.save_graph(&graph)?;
+                        let name = graph.get(id).map(|p| p.name.clone()).unwrap_or_default();
+                        Ok(ToolOutput::text(format!(
+                            "Logged {} interaction for '{}'.",
+                            kind, name
+                        )))
+                    }
+                    Err(_) => Ok(ToolOutput::text(format!("Contact #{} not found.", id))),
+                }
+            }
+            "timeline" => {
+                let id = arg_id(&args, "id");
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+                match graph.get(id) {
+                    Some(person) => Ok(ToolOutput::text(timeline(person, limit))),
+                    None => Ok(ToolOutput::text(format!("Contact #{} not found.", id))),
+                }
+            }
+            "suggestions" => {
+                let defaults = SuggestionConfig::default();
+                let config = SuggestionConfig {
+                    reply_after_days: args
+                        .get("reply_after_days")
+                        .and_then(|v| v.as_i64())
+                        .unwrap_or(defaults.reply_after_days),
+                    stale_after_days: args
+                        .get("stale_after_days")
+                        .and_then(|v| v.as_i64())
+                        .unwrap_or(defaults.stale_after_days),
+                    ..defaults
+                };
+                let suggestions = graph.suggestions(Utc::now(), &config);
+                if suggestions.is_empty() {
+                    return Ok(ToolOutput::text("Nobody is waiting to hear from you."));
+                }
+                let lines: Vec<String> = suggestions
+                    .iter()
+                    .map(|s| format!("  #{} — {}", s.person_id, s.message))
+                    .collect();
+                Ok(ToolOutput::text(format!(
+                    "Suggestions ({}):\n{}",
+                    suggestions.len(),
+                    lines.join("\n")
+                )))
+            }
+            "merge" => {
+                let id = arg_id(&args, "id");
+                let other = arg_id(&args, "other_id");
+                match graph.merge(id, other) {
+                    Ok(()) => {
+                        self.save_graph(&graph)?;
+                        Ok(ToolOutput::text(format!("Merged #{} into #{}.", other, id)))
+                    }
+                    Err(e) => Ok(ToolOutput::text(e.to_string())),
+                }
+            }
+            "split" => {
+                let id = arg_id(&args, "id");
+                let identifiers: Vec<String> = args
+                    .get("identifiers")
+                    .and_then(|v| v.as_array())
+                    .map(|a| {
+                        a.iter()
+                            .filter_map(|v| v.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if identifiers.is_empty() {
+                    return Ok(ToolOutput::text(
+                        "Please provide the identifiers to split off.",
+                    ));
+                }
+                let name = args.get("name").and_then(|v| v.as_str());
+                match graph.split(id, &identifiers, name) {
+                    Ok(new_id) => {
+                        self.save_graph(&graph)?;
+                        Ok(ToolOutput::text(format!(
+                            "Split {} off #{} into new person #{}.",
+                            identifiers.join(", "),
+                            id,
+                            new_id
+                        )))
+                    }
+                    Err(e) => Ok(ToolOutput::text(e.to_string())),
+                }
+            }
+            "forget" => {
+                let id = arg_id(&args, "id");
+                match graph.forget(id) {
+                    Ok(person) => {
+                        self.save_graph(&graph)?;
+                        Ok(ToolOutput::text(format!(
+                            "Forgot '{}' (#{}) and {} interactions.",
+                            person.name,
+                            id,
+                            person.interactions.len()
+                        )))
+                    }
+                    Err(PeopleError::NotFound { .. }) => {
+                        Ok(ToolOutput::text(format!("Contact #{} not found.", id)))
+                    }
+                    Err(e) => Err(failed(e)),
+                }
+            }
+            "export" => {
+                let id = arg_id(&args, "id");
+                match graph.export(id) {
+                    Ok(value) => Ok(ToolOutput::text(
+                        serde_json::to_string_pretty(&value).map_err(failed)?,
+                    )),
+                    Err(PeopleError::NotFound { .. }) => {
+                        Ok(ToolOutput::text(format!("Contact #{} not found.", id)))
+                    }
+                    Err(e) => Err(failed(e)),
                 }
             }
             _ => Ok(ToolOutput::text(format!(
-                "Unknown action: {}. Use: add_contact, update, search, list, log_interaction",
+                "Unknown action: {}. Use: add_contact, update, search, list, log_interaction, \
+                 timeline, suggestions, merge, split, forget, export",
                 action
             ))),
         }
@@ -337,4 +554,55 @@ mod tests {
         assert_eq!(tool.name(), "relationships");
         assert!(tool.parameters_schema().get("properties").is_some());
     }
+
+    #[tokio::test]
+    async fn test_relationships_links_channel_people_and_migrates() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let legacy = workspace.join(".rustant").join("relationships");
+        std::fs::create_dir_all(&legacy).unwrap();
+        std::fs::write(
+            legacy.join("contacts.json"),
+            r#"{"contacts": [{"id": 1, "name": "Priya", "email": "priya@example.com",
+                "interactions": [], "created_at": "2026-01-01T00:00:00Z"}], "next_id": 2}"#,
+        )
+        .unwrap();
+        let mut graph = PersonGraph::default();
+        graph.record_incoming(
+            PersonObservation::from_handle("email", "priya@example.com"),
+            Utc::now() - chrono::Duration::days(10),
+            "Could you send the Q3 report?",
+            Some("question".into()),
+            true,
+        );
+        graph.save(&workspace).unwrap();
+
+        let tool = RelationshipsTool::new(workspace.clone());
+        let list = tool.execute(json!({"action": "list"})).await.unwrap();
+        assert!(list.content.contains("Contacts (1)"));
+        assert!(legacy.join("contacts.json.migrated").exists());
+
+        let timeline = tool
+            .execute(json!({"action": "timeline", "id": 1}))
+            .await
+            .unwrap();
+        assert!(timeline.content.contains("relationships:Priya"));
+        assert!(timeline.content.contains("Awaiting your reply"));
+        let suggestions = tool
+            .execute(json!({"action": "suggestions"}))
+            .await
+            .unwrap();
+        assert!(suggestions.content.contains("Q3 report"));
+
+        let exported = tool
+            .execute(json!({"action": "export", "id": 1}))
+            .await
+            .unwrap();
+        assert!(exported.content.contains("priya@example.com"));
+        tool.execute(json!({"action": "forget", "id": 1}))
+            .await
+            .unwrap();
+        let list = tool.execute(json!({"action": "list"})).await.unwrap();
+        assert!(list.content.contains("No contacts"));
+    }
 }
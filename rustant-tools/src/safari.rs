@@ -2,20 +2,35 @@
 //!
 //! Provides navigation, tab management, text extraction, and JavaScript
 //! execution in Safari for users who prefer it over Chromium-based browsers.
+//! The `capture` action saves the current page through [`ReaderCapture`].
 //! macOS only.
 
 use crate::macos::{require_str, run_osascript, sanitize_applescript_string};
+use crate::reader_capture::{CapturedPage, ReaderCapture, tags_arg};
 use crate::registry::Tool;
 use async_trait::async_trait;
 use rustant_core::error::ToolError;
 use rustant_core::types::{RiskLevel, ToolOutput};
 use serde_json::json;
+use std::path::PathBuf;
 use std::time::Duration;
 use tracing::debug;
 
 const TOOL_NAME: &str = "macos_safari";
+/// Separates URL, title and HTML in the capture script's output.
+const CAPTURE_SEPARATOR: &str = "<<RUSTANT-CAPTURE>>";
 
-pub struct MacosSafariTool;
+pub struct MacosSafariTool {
+    capture: ReaderCapture,
+}
+
+impl MacosSafariTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self {
+            capture: ReaderCapture::new(workspace),
+        }
+    }
+}
 
 #[async_trait]
 impl Tool for MacosSafariTool {
@@ -26,7 +41,9 @@ impl Tool for MacosSafariTool {
     fn description(&self) -> &str {
         "Control Safari browser on macOS. Actions: navigate (open URL), get_url (current URL), \
          get_text (page text content), run_javascript (execute JS), list_tabs (all tabs), \
-         new_tab (open new tab)."
+         new_tab (open new tab), capture (save the current page's reader-mode content to \
+         .rustant/research/captures and the knowledge graph), list_captures (saved articles, \
+         optionally by tag)."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -35,7 +52,7 @@ impl Tool for MacosSafariTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["navigate", "get_url", "get_text", "run_javascript", "list_tabs", "new_tab"],
+                    "enum": ["navigate", "get_url", "get_text", "run_javascript", "list_tabs", "new_tab", "capture", "list_captures"],
                     "description": "Action to perform"
                 },
                 "url": {
@@ -45,6 +62,19 @@ impl Tool for MacosSafariTool {
                 "script": {
                     "type": "string",
                     "description": "JavaScript code to execute (for run_javascript)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Extra topic tags (for capture)"
+                },
+                "tag": {
+                    "type": "string",
+                    "description": "Only list captures with this tag (for list_captures)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum captures to list (for list_captures, default 20)"
                 }
             },
             "required": ["action"]
@@ -61,11 +91,13 @@ impl Tool for MacosSafariTool {
             "run_javascript" => execute_run_javascript(&args).await,
             "list_tabs" => execute_list_tabs().await,
             "new_tab" => execute_new_tab(&args).await,
+            "capture" => self.execute_capture(&args).await,
+            "list_captures" => Ok(self.capture.list_output(&args)),
             other => Err(ToolError::InvalidArguments {
                 name: TOOL_NAME.to_string(),
                 reason: format!(
                     "unknown action '{other}'. Valid: navigate, get_url, get_text, \
-                     run_javascript, list_tabs, new_tab"
+                     run_javascript, list_tabs, new_tab, capture, list_captures"
                 ),
             }),
         }
@@ -80,6 +112,49 @@ impl Tool for MacosSafariTool {
     }
 }
 
+impl MacosSafariTool {
+    async fn execute_capture(&self, args: &serde_json::Value) -> Result<ToolOutput, ToolError> {
+        debug!("Capturing Safari page");
+
+        let script = format!(
+            r#"
+tell application "Safari"
+    if (count of windows) is 0 then
+        error "No Safari windows open."
+    end if
+    set pageUrl to URL of document 1
+    set pageTitle to name of document 1
+    set pageHtml to do JavaScript "document.documentElement.outerHTML" in document 1
+    return pageUrl & "{CAPTURE_SEPARATOR}" & pageTitle & "{CAPTURE_SEPARATOR}" & pageHtml
+end tell
+"#
+        );
+
+        let failed = |message: String| ToolError::ExecutionFailed {
+            name: TOOL_NAME.to_string(),
+            message,
+        };
+        let result = run_osascript(&script).await.map_err(failed)?;
+        let mut parts = result.splitn(3, CAPTURE_SEPARATOR);
+        let (Some(url), Some(title), Some(html)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(failed("Safari returned no page content".to_string()));
+        };
+        let page = CapturedPage {
+            url: url.trim().to_string(),
+            title: title.trim().to_string(),
+            html: html.to_string(),
+        };
+
+        let outcome = self
+            .capture
+            .capture(page, &tags_arg(args))
+            .await
+            .map_err(failed)?;
+        Ok(ToolOutput::text(outcome.summary()))
+    }
+}
+
 async fn execute_navigate(args: &serde_json::Value) -> Result<ToolOutput, ToolError> {
     let url = sanitize_applescript_string(require_str(args, "url", TOOL_NAME)?);
     debug!(url = %url, "Navigating Safari");
@@ -276,35 +351,36 @@ mod tests {
 
     #[test]
     fn test_safari_name() {
-        let tool = MacosSafariTool;
+        let tool = MacosSafariTool::new(std::env::temp_dir());
         assert_eq!(tool.name(), "macos_safari");
     }
 
     #[test]
     fn test_safari_risk_level() {
-        let tool = MacosSafariTool;
+        let tool = MacosSafariTool::new(std::env::temp_dir());
         assert_eq!(tool.risk_level(), RiskLevel::Write);
     }
 
     #[test]
     fn test_safari_timeout() {
-        let tool = MacosSafariTool;
+        let tool = MacosSafariTool::new(std::env::temp_dir());
         assert_eq!(tool.timeout(), Duration::from_secs(15));
     }
 
     #[test]
     fn test_safari_schema() {
-        let tool = MacosSafariTool;
+        let tool = MacosSafariTool::new(std::env::temp_dir());
         let schema = tool.parameters_schema();
         let props = schema["properties"].as_object().unwrap();
         assert!(props.contains_key("action"));
         assert!(props.contains_key("url"));
         assert!(props.contains_key("script"));
+        assert!(props.contains_key("tags"));
     }
 
     #[tokio::test]
     async fn test_safari_missing_action() {
-        let tool = MacosSafariTool;
+        let tool = MacosSafariTool::new(std::env::temp_dir());
         let result = tool.execute(json!({})).await;
         assert!(result.is_err());
         match result.unwrap_err() {
@@ -318,7 +394,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_safari_invalid_action() {
-        let tool = MacosSafariTool;
+        let tool = MacosSafariTool::new(std::env::temp_dir());
         let result = tool.execute(json!({"action": "bad"})).await;
         assert!(result.is_err());
         match result.unwrap_err() {
@@ -26,8 +26,9 @@ impl Tool for MacosScreenAnalyzeTool {
 
     fn description(&self) -> &str {
         "Analyze screen content via OCR. Actions: ocr (extract text from a screenshot \
-         of the screen or a specific app window), find_on_screen (find text location \
-         on screen). Uses macOS Vision framework for text recognition."
+         of the screen or a specific app window, or from an image attachment), \
+         find_on_screen (find text location on screen). Uses macOS Vision framework for \
+         text recognition."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -46,6 +47,10 @@ impl Tool for MacosScreenAnalyzeTool {
                 "description": {
                     "type": "string",
                     "description": "Text or element to find on screen (for find_on_screen)"
+                },
+                "image": {
+                    "type": "string",
+                    "description": "Attachment handle (att-...) or path of an image to read instead of capturing the screen (for ocr)"
                 }
             },
             "required": ["action"]
@@ -243,15 +248,21 @@ async fn execute_ocr(args: &serde_json::Value) -> Result<ToolOutput, ToolError>
     let app_name = args["app_name"].as_str();
     debug!(app = ?app_name, "Performing OCR");
 
-    let screenshot_path = capture_screenshot(app_name).await?;
-    let text = extract_text_from_image(&screenshot_path).await?;
+    let (text, source) = if let Some(image) = args["image"].as_str() {
+        let (path, source) = resolve_image(image)?;
+        (extract_text_from_image(&path).await?, source)
+    } else {
+        let screenshot_path = capture_screenshot(app_name).await?;
+        let text = extract_text_from_image(&screenshot_path).await?;
 
-    // Clean up temp file
-    let _ = tokio::fs::remove_file(&screenshot_path).await;
+        // Clean up temp file
+        let _ = tokio::fs::remove_file(&screenshot_path).await;
 
-    let source = app_name
-        .map(|a| format!("'{a}' window"))
-        .unwrap_or_else(|| "full screen".to_string());
+        let source = app_name
+            .map(|a| format!("'{a}' window"))
+            .unwrap_or_else(|| "full screen".to_string());
+        (text, source)
+    };
 
     // Truncate if too long
     let truncated = if text.len() > 4000 {
@@ -269,6 +280,29 @@ async fn execute_ocr(args: &serde_json::Value) -> Result<ToolOutput, ToolError>
     )))
 }
 
+/// Resolve the `image` argument, an attachment handle or a file path, to a
+/// readable path and a label for the output.
+fn resolve_image(image: &str) -> Result<(String, String), ToolError> {
+    if !rustant_core::attachments::is_handle(image) {
+        return Ok((image.to_string(), image.to_string()));
+    }
+    let (handle, path) =
+        rustant_core::attachments::resolve(image).map_err(|e| ToolError::InvalidArguments {
+            name: TOOL_NAME.to_string(),
+            reason: e.to_string(),
+        })?;
+    if !handle.mime_type.starts_with("image/") {
+        return Err(ToolError::InvalidArguments {
+            name: TOOL_NAME.to_string(),
+            reason: format!("attachment {} is {}, not an image", image, handle.mime_type),
+        });
+    }
+    Ok((
+        path.to_string_lossy().into_owned(),
+        format!("{} ({})", handle.description, handle.id),
+    ))
+}
+
 async fn execute_find_on_screen(args: &serde_json::Value) -> Result<ToolOutput, ToolError> {
     let description = args["description"]
         .as_str()
@@ -3,8 +3,10 @@
 use crate::registry::Tool;
 use async_trait::async_trait;
 use rustant_core::error::ToolError;
-use rustant_core::types::{ProgressUpdate, RiskLevel, ToolOutput};
-use std::path::PathBuf;
+use rustant_core::gateway::{PtyManager, PtySpawn};
+use rustant_core::types::{ProgressUpdate, RiskLevel, ToolErrorKind, ToolFailure, ToolOutput};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::sync::mpsc;
@@ -12,11 +14,14 @@ use tracing::{debug, warn};
 
 /// Execute shell commands within the workspace.
 ///
-/// Supports optional streaming of stdout/stderr lines via a progress channel.
+/// Supports optional streaming of stdout/stderr lines via a progress channel,
+/// and running commands flagged as interactive in a gateway PTY session.
 pub struct ShellExecTool {
     workspace: PathBuf,
     /// Optional channel for streaming progress updates (shell output lines).
     progress_tx: Option<mpsc::UnboundedSender<ProgressUpdate>>,
+    /// Gateway PTY sessions for interactive commands.
+    pty: Option<Arc<PtyManager>>,
 }
 
 impl ShellExecTool {
@@ -24,6 +29,7 @@ impl ShellExecTool {
         Self {
             workspace,
             progress_tx: None,
+            pty: None,
         }
     }
 
@@ -32,8 +38,16 @@ impl ShellExecTool {
         Self {
             workspace,
             progress_tx: Some(tx),
+            pty: None,
         }
     }
+
+    /// Run commands flagged `interactive` in PTY sessions the user can type
+    /// into from the dashboard's terminal panel.
+    pub fn with_pty(mut self, pty: Arc<PtyManager>) -> Self {
+        self.pty = Some(pty);
+        self
+    }
 }
 
 #[async_trait]
@@ -56,7 +70,19 @@ impl Tool for ShellExecTool {
                 },
                 "working_dir": {
                     "type": "string",
-                    "description": "Working directory (relative to workspace). Defaults to workspace root."
+                    "description": "Working directory (relative to workspace, or to the subproject when one is given). Defaults to workspace root."
+                },
+                "subproject": {
+                    "type": "string",
+                    "description": "In a monorepo, the subproject (name or path) to run the command in, e.g. 'frontend'."
+                },
+                "interactive": {
+                    "type": "boolean",
+                    "description": "Set for commands that prompt for input (passwords, confirmations, editors). When a dashboard is attached, the command runs in a terminal the user can type into."
+                },
+                "interactive_reason": {
+                    "type": "string",
+                    "description": "Why the command needs the user's input, shown in the terminal panel."
                 }
             },
             "required": ["command"]
@@ -71,14 +97,43 @@ impl Tool for ShellExecTool {
                 reason: "'command' parameter is required".into(),
             })?;
 
+        // The agent normally resolves `subproject` into `working_dir`; this
+        // covers callers that reach the tool directly.
+        let base = match args["subproject"].as_str() {
+            Some(name) => {
+                let subprojects = rustant_core::project_detect::detect_subprojects(&self.workspace);
+                let sub = rustant_core::project_detect::route_to_subproject(
+                    &subprojects,
+                    Some(name),
+                    command,
+                    &[],
+                )
+                .map_err(|reason| ToolError::InvalidArguments {
+                    name: "shell_exec".into(),
+                    reason,
+                })?;
+                sub.map(|s| self.workspace.join(&s.path))
+                    .unwrap_or_else(|| self.workspace.clone())
+            }
+            None => self.workspace.clone(),
+        };
         let working_dir = if let Some(dir) = args["working_dir"].as_str() {
-            self.workspace.join(dir)
+            base.join(dir)
         } else {
-            self.workspace.clone()
+            base
         };
 
         debug!(command = command, cwd = %working_dir.display(), "Executing shell command");
 
+        if args["interactive"].as_bool().unwrap_or(false)
+            && let Some(pty) = &self.pty
+        {
+            let reason = args["interactive_reason"].as_str().map(String::from);
+            return self
+                .execute_interactive(pty, command, &working_dir, reason)
+                .await;
+        }
+
         // If we have a progress sender, stream output line by line
         if let Some(ref tx) = self.progress_tx {
             self.execute_streaming(command, &working_dir, tx).await
@@ -92,7 +147,12 @@ impl Tool for ShellExecTool {
     }
 
     fn timeout(&self) -> Duration {
-        Duration::from_secs(120)
+        // Interactive commands wait on the user.
+        if self.pty.is_some() {
+            Duration::from_secs(600)
+        } else {
+            Duration::from_secs(120)
+        }
     }
 }
 
@@ -113,9 +173,12 @@ impl ShellExecTool {
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .spawn()
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "shell_exec".into(),
-                message: format!("Failed to execute command: {}", e),
+            .map_err(|e| {
+                ToolError::failed(
+                    "shell_exec",
+                    format!("Failed to execute command: {}", e),
+                    ToolFailure::from_io(&e),
+                )
             })?;
 
         // Send initial progress
@@ -168,9 +231,12 @@ impl ShellExecTool {
         });
 
         // Wait for the process to complete
-        let status = child.wait().await.map_err(|e| ToolError::ExecutionFailed {
-            name: "shell_exec".into(),
-            message: format!("Failed to wait for command: {}", e),
+        let status = child.wait().await.map_err(|e| {
+            ToolError::failed(
+                "shell_exec",
+                format!("Failed to wait for command: {}", e),
+                ToolFailure::from_io(&e),
+            )
         })?;
 
         // Collect output from tasks
@@ -207,6 +273,67 @@ impl ShellExecTool {
             );
         }
 
+        Ok(exit_output(result, exit_code))
+    }
+
+    /// Execute a command in a gateway PTY session and wait for it to exit,
+    /// returning before the tool timeout if the user has not finished.
+    async fn execute_interactive(
+        &self,
+        pty: &Arc<PtyManager>,
+        command: &str,
+        working_dir: &Path,
+        reason: Option<String>,
+    ) -> Result<ToolOutput, ToolError> {
+        let session = pty
+            .spawn(PtySpawn {
+                command: command.to_string(),
+                cwd: working_dir.to_path_buf(),
+                reason,
+                journal_dir: Some(self.workspace.join(".rustant").join("terminal")),
+                cols: 120,
+                rows: 32,
+            })
+            .map_err(|e| ToolError::ExecutionFailed {
+                name: "shell_exec".into(),
+                message: e.to_string(),
+            })?;
+
+        if let Some(tx) = &self.progress_tx {
+            let _ = tx.send(ProgressUpdate::ToolProgress {
+                tool: "shell_exec".into(),
+                stage: format!("waiting in terminal: {}", truncate_cmd(command, 50)),
+                percent: None,
+            });
+        }
+
+        let wait = self.timeout().saturating_sub(Duration::from_secs(10));
+        let exit = pty
+            .wait(&session.id, wait)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed {
+                name: "shell_exec".into(),
+                message: e.to_string(),
+            })?;
+        let transcript = pty.transcript(&session.id).unwrap_or_default();
+        let transcript = if transcript.trim().is_empty() {
+            "(empty)"
+        } else {
+            transcript.as_str()
+        };
+
+        let result = match exit {
+            Some(exit) => format!(
+                "Exit code: {}\n\n--- terminal ---\n{}",
+                exit.exit_code.unwrap_or(-1),
+                transcript
+            ),
+            None => format!(
+                "Command is still running in terminal session {}; the user can finish \
+                 it from the dashboard's terminal panel.\n\n--- terminal so far ---\n{}",
+                session.id, transcript
+            ),
+        };
         Ok(ToolOutput::text(result))
     }
 
@@ -222,9 +349,12 @@ impl ShellExecTool {
             .current_dir(working_dir)
             .output()
             .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "shell_exec".into(),
-                message: format!("Failed to execute command: {}", e),
+            .map_err(|e| {
+                ToolError::failed(
+                    "shell_exec",
+                    format!("Failed to execute command: {}", e),
+                    ToolFailure::from_io(&e),
+                )
             })?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -253,10 +383,25 @@ impl ShellExecTool {
             );
         }
 
-        Ok(ToolOutput::text(result))
+        Ok(exit_output(result, exit_code))
     }
 }
 
+/// The output for a finished command. Exit codes the shell uses for a
+/// missing (127) or non-executable (126) command are reported as failures;
+/// other non-zero codes are ordinary results for the model to read.
+fn exit_output(result: String, exit_code: i32) -> ToolOutput {
+    let failure = match exit_code {
+        127 => ToolFailure::new(ToolErrorKind::NotFound).with_remediation(
+            "The command is not installed or not on PATH; check its name or install it first.",
+        ),
+        126 => ToolFailure::new(ToolErrorKind::PermissionDenied)
+            .with_remediation("The command is not executable; check its permissions."),
+        _ => return ToolOutput::text(result),
+    };
+    ToolOutput::failure(result, failure)
+}
+
 /// Truncate a command string for display.
 fn truncate_cmd(cmd: &str, max: usize) -> String {
     if cmd.len() <= max {
@@ -291,6 +436,24 @@ mod tests {
         assert!(result.content.contains("Exit code: 0"));
     }
 
+    #[tokio::test]
+    async fn test_shell_exec_missing_command_is_not_found() {
+        let dir = setup_workspace();
+        let tool = ShellExecTool::new(dir.path().to_path_buf());
+
+        let result = tool
+            .execute(serde_json::json!({"command": "definitely-not-a-command-xyz"}))
+            .await
+            .unwrap();
+        assert_eq!(result.error.unwrap().kind, ToolErrorKind::NotFound);
+
+        let result = tool
+            .execute(serde_json::json!({"command": "exit 3"}))
+            .await
+            .unwrap();
+        assert!(result.error.is_none());
+    }
+
     #[tokio::test]
     async fn test_shell_exec_with_cwd() {
         let dir = setup_workspace();
@@ -439,6 +602,27 @@ mod tests {
         assert!(!required.contains(&serde_json::json!("working_dir")));
     }
 
+    #[tokio::test]
+    async fn test_shell_exec_interactive_uses_pty() {
+        let dir = setup_workspace();
+        let (events, _) = tokio::sync::broadcast::channel(64);
+        let pty = Arc::new(PtyManager::new(Default::default(), events));
+        let tool = ShellExecTool::new(dir.path().to_path_buf()).with_pty(Arc::clone(&pty));
+
+        let result = tool
+            .execute(serde_json::json!({
+                "command": "test -t 0 && echo on a tty",
+                "interactive": true,
+                "interactive_reason": "needs a terminal"
+            }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("Exit code: 0"));
+        assert!(result.content.contains("on a tty"));
+        assert_eq!(pty.list()[0].reason.as_deref(), Some("needs a terminal"));
+        assert!(dir.path().join(".rustant/terminal").is_dir());
+    }
+
     #[tokio::test]
     async fn test_shell_exec_empty_command() {
         let dir = setup_workspace();
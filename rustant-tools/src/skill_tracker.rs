@@ -699,7 +699,7 @@ impl Tool for SkillTrackerTool {
                     })
                     .collect();
 
-                scored.sort_by(|a, b| b.1.cmp(&a.1));
+                scored.sort_by_key(|b| std::cmp::Reverse(b.1));
                 let top3: Vec<&(&Skill, u32)> = scored.iter().take(3).collect();
 
                 if top3.is_empty() {
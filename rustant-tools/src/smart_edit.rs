@@ -5,12 +5,14 @@
 //! with unified diff output and auto-checkpoint support.
 
 use crate::checkpoint::CheckpointManager;
+use crate::lsp::pre_write::{PreWriteCheck, PreWriteOutcome, pre_write_check};
 use crate::registry::Tool;
 use async_trait::async_trait;
 use rustant_core::error::ToolError;
 use rustant_core::types::{Artifact, RiskLevel, ToolOutput};
 use similar::TextDiff;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::debug;
 
@@ -19,6 +21,7 @@ use tracing::debug;
 pub struct SmartEditTool {
     workspace: PathBuf,
     checkpoint_mgr: Mutex<CheckpointManager>,
+    pre_write: Option<Arc<PreWriteCheck>>,
 }
 
 impl SmartEditTool {
@@ -27,8 +30,15 @@ impl SmartEditTool {
         Self {
             workspace,
             checkpoint_mgr: Mutex::new(checkpoint_mgr),
+            pre_write: pre_write_check(),
         }
     }
+
+    /// Check edited code with `check` before writing it.
+    pub fn with_pre_write_check(mut self, check: Arc<PreWriteCheck>) -> Self {
+        self.pre_write = Some(check);
+        self
+    }
 }
 
 /// Supported edit operation types.
@@ -56,6 +66,24 @@ impl EditType {
     }
 }
 
+/// Lowest score at which a one-line fuzzy match is applied. One-line
+/// locations are often descriptions ("timeout field"), so this is loose.
+const LINE_MATCH_THRESHOLD: f64 = 0.25;
+
+/// Lowest score at which multi-line search text is applied. Multi-line
+/// search text is copied from the file, so it has to be close.
+const BLOCK_MATCH_THRESHOLD: f64 = 0.6;
+
+/// Matches scoring within this margin of the best one are treated as
+/// equally likely, and the edit is refused rather than guessed.
+const AMBIGUITY_MARGIN: f64 = 0.1;
+
+/// How many lines either side of a text anchor a match may start.
+const ANCHOR_RADIUS: usize = 40;
+
+/// Candidates listed when a location is ambiguous.
+const MAX_LISTED_CANDIDATES: usize = 5;
+
 /// A located match within a file.
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -68,25 +96,134 @@ struct LocationMatch {
     matched_text: String,
     /// Line number (1-based) where match starts.
     line_number: usize,
+    /// Line number (1-based) where match ends.
+    end_line: usize,
     /// Context lines around the match for preview.
     context_preview: String,
+    /// Which strategy found the match ("exact", "line range", "definition"
+    /// or "fuzzy").
+    strategy: &'static str,
+    /// How closely the matched text fits the location, from 0 to 1. Only
+    /// fuzzy matches score below 1.
+    confidence: f64,
 }
 
-/// Find a location in file content using a search pattern.
-/// Supports exact text, line-number patterns ("line 42"), and fuzzy substring matching.
-fn find_location(content: &str, pattern: &str) -> Result<LocationMatch, String> {
-    // Strategy 1: Try exact match first
-    if let Some(start) = content.find(pattern) {
-        let end = start + pattern.len();
-        let line_number = content[..start].matches('\n').count() + 1;
-        let preview = extract_context(content, start, end, 2);
-        return Ok(LocationMatch {
+impl LocationMatch {
+    fn new(
+        content: &str,
+        start: usize,
+        end: usize,
+        strategy: &'static str,
+        confidence: f64,
+        context_lines: usize,
+    ) -> Self {
+        Self {
             start,
             end,
-            matched_text: pattern.to_string(),
-            line_number,
-            context_preview: preview,
+            matched_text: content[start..end].to_string(),
+            line_number: line_at(content, start),
+            end_line: line_at(content, end.saturating_sub(1).max(start)),
+            context_preview: extract_context(content, start, end, context_lines),
+            strategy,
+            confidence,
+        }
+    }
+
+    /// "line 4" or "lines 4-9".
+    fn lines_label(&self) -> String {
+        if self.end_line > self.line_number {
+            format!("lines {}-{}", self.line_number, self.end_line)
+        } else {
+            format!("line {}", self.line_number)
+        }
+    }
+}
+
+/// Narrows where a location may match, from the `anchor` parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Anchor {
+    /// Matches must start within these lines (1-based, inclusive).
+    Lines(usize, usize),
+    /// Matches must start within `ANCHOR_RADIUS` lines of this line, and
+    /// the nearest one wins.
+    Near(usize),
+}
+
+impl Anchor {
+    /// Parse an anchor: a line number or range ("42", "10-20", "lines
+    /// 10-20"), or a string that appears exactly once in `content`.
+    fn resolve(content: &str, anchor: &str) -> Result<Self, String> {
+        let anchor = anchor.trim();
+        if anchor.is_empty() {
+            return Err("'anchor' is empty".into());
+        }
+
+        let range = parse_line_pattern(anchor).or_else(|| {
+            let (first, last) = anchor.split_once('-').unwrap_or((anchor, anchor));
+            Some((first.trim().parse().ok()?, last.trim().parse().ok()?))
         });
+        if let Some((first, last)) = range {
+            if first == 0 || last < first {
+                return Err(format!("Anchor '{}' is not a valid line range", anchor));
+            }
+            return Ok(Self::Lines(first, last));
+        }
+
+        let lines: Vec<usize> = content
+            .match_indices(anchor)
+            .map(|(i, _)| line_at(content, i))
+            .collect();
+        match lines.as_slice() {
+            [] => Err(format!(
+                "Anchor '{}' does not appear in the file",
+                truncate(anchor, 80)
+            )),
+            [line] => Ok(Self::Near(*line)),
+            _ => Err(format!(
+                "Anchor '{}' appears {} times (lines {}). Use a string that appears once, or a line range.",
+                truncate(anchor, 80),
+                lines.len(),
+                lines
+                    .iter()
+                    .map(|l| l.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    /// Whether a match starting on `line` is inside the anchor's window.
+    fn allows(&self, line: usize) -> bool {
+        match *self {
+            Self::Lines(first, last) => (first..=last).contains(&line),
+            Self::Near(at) => line.abs_diff(at) <= ANCHOR_RADIUS,
+        }
+    }
+}
+
+/// Find a location in file content using a search pattern.
+/// Supports exact text, line-number patterns ("line 42"), function names, and
+/// fuzzy matching. When several places match equally well the location is
+/// refused and every candidate is listed; an `anchor` narrows the search.
+fn find_location(
+    content: &str,
+    pattern: &str,
+    anchor: Option<Anchor>,
+) -> Result<LocationMatch, String> {
+    if pattern.trim().is_empty() {
+        return Err("Location is empty".into());
+    }
+
+    // Strategy 1: Exact match, refused if the text occurs more than once
+    let exact: Vec<LocationMatch> = content
+        .match_indices(pattern)
+        .map(|(start, _)| {
+            LocationMatch::new(content, start, start + pattern.len(), "exact", 1.0, 2)
+        })
+        .filter(|m| anchor.is_none_or(|a| a.allows(m.line_number)))
+        .collect();
+    if !exact.is_empty() {
+        return choose(pattern, exact, anchor);
     }
 
     // Strategy 2: Line number pattern (e.g., "line 42", "lines 10-20")
@@ -94,20 +231,69 @@ fn find_location(content: &str, pattern: &str) -> Result<LocationMatch, String>
         return find_by_line_range(content, m.0, m.1);
     }
 
-    // Strategy 3: Function/method name pattern (e.g., "fn handle_request", "def process")
-    if let Some(m) = find_by_function_pattern(content, pattern) {
-        return Ok(m);
+    // Strategy 3: Function/method name pattern (e.g., "fn handle_request", "def process").
+    // Multi-line patterns are search text, not names, so they skip this.
+    let definitions: Vec<LocationMatch> = if pattern.trim().contains('\n') {
+        Vec::new()
+    } else {
+        find_by_function_pattern(content, pattern)
+            .into_iter()
+            .filter(|m| anchor.is_none_or(|a| a.allows(m.line_number)))
+            .collect()
+    };
+    if !definitions.is_empty() {
+        return choose(pattern, definitions, anchor);
     }
 
-    // Strategy 4: Fuzzy line-by-line matching using similarity scoring
-    if let Some(m) = find_by_fuzzy_match(content, pattern) {
-        return Ok(m);
+    // Strategy 4: Fuzzy matching using similarity scoring
+    find_by_fuzzy_match(content, pattern, anchor)
+}
+
+/// Pick the one match among `candidates`, which all fit about equally well.
+/// A text anchor keeps the nearest; any remaining tie is an error listing
+/// every candidate so the caller can disambiguate.
+fn choose(
+    pattern: &str,
+    mut candidates: Vec<LocationMatch>,
+    anchor: Option<Anchor>,
+) -> Result<LocationMatch, String> {
+    if let Some(Anchor::Near(line)) = anchor {
+        let nearest = candidates
+            .iter()
+            .map(|m| m.line_number.abs_diff(line))
+            .min()
+            .unwrap_or(0);
+        candidates.retain(|m| m.line_number.abs_diff(line) == nearest);
     }
 
-    Err(format!(
-        "Could not locate '{}' in the file. Try using exact text, a line number (e.g., 'line 42'), or a function name.",
-        truncate(pattern, 80)
-    ))
+    if candidates.len() == 1 {
+        return Ok(candidates.remove(0));
+    }
+
+    let mut message = format!(
+        "'{}' matches {} locations equally well, so no edit was made. Add surrounding lines to \
+         the location, or pass 'anchor' with a unique nearby string or a line range \
+         (e.g. 'lines 10-20').",
+        truncate(pattern, 80),
+        candidates.len()
+    );
+    for (i, m) in candidates.iter().take(MAX_LISTED_CANDIDATES).enumerate() {
+        message.push_str(&format!(
+            "\n\nCandidate {}: {} ({} match, confidence {:.2})\n{}",
+            i + 1,
+            m.lines_label(),
+            m.strategy,
+            m.confidence,
+            m.context_preview
+        ));
+    }
+    if candidates.len() > MAX_LISTED_CANDIDATES {
+        message.push_str(&format!(
+            "\n\n...and {} more",
+            candidates.len() - MAX_LISTED_CANDIDATES
+        ));
+    }
+    Err(message)
 }
 
 /// Parse "line N" or "lines N-M" patterns.
@@ -172,20 +358,20 @@ fn find_by_line_range(
         }
     }
 
-    let matched = &content[start_byte..end_byte];
-    let preview = extract_context(content, start_byte, end_byte, 1);
-
-    Ok(LocationMatch {
-        start: start_byte,
-        end: end_byte,
-        matched_text: matched.to_string(),
-        line_number: start_line,
-        context_preview: preview,
-    })
+    Ok(LocationMatch::new(
+        content,
+        start_byte,
+        end_byte,
+        "line range",
+        1.0,
+        1,
+    ))
 }
 
-/// Find a function or block by pattern matching common language constructs.
-fn find_by_function_pattern(content: &str, pattern: &str) -> Option<LocationMatch> {
+/// Find function or block definitions by pattern matching common language
+/// constructs. Definitions naming the identifier exactly win over ones that
+/// merely contain it.
+fn find_by_function_pattern(content: &str, pattern: &str) -> Vec<LocationMatch> {
     let pattern_lower = pattern.to_lowercase();
 
     // Common function signature prefixes
@@ -210,40 +396,48 @@ fn find_by_function_pattern(content: &str, pattern: &str) -> Option<LocationMatc
         || pattern_lower.contains(" method");
 
     if !is_fn_pattern {
-        return None;
+        return Vec::new();
     }
 
     // Extract function name from pattern
     let name = extract_identifier_from_pattern(&pattern_lower);
     if name.is_empty() {
-        return None;
+        return Vec::new();
     }
 
-    // Search for function definition containing this name
+    // Search for function definitions containing this name
+    let spans = line_spans(content);
+    let mut exact = Vec::new();
+    let mut partial = Vec::new();
     for (i, line) in content.lines().enumerate() {
         let line_lower = line.to_lowercase();
         let has_fn_keyword = fn_prefixes.iter().any(|p| line_lower.contains(p));
 
         if has_fn_keyword && line_lower.contains(&name) {
-            let byte_start: usize = content.lines().take(i).map(|l| l.len() + 1).sum();
+            let byte_start = spans[i].0;
 
             // Find the end of the function block (matching braces or indentation)
             let block_end = find_block_end(content, byte_start);
 
-            let matched = &content[byte_start..block_end];
-            let preview = extract_context(content, byte_start, block_end, 0);
-
-            return Some(LocationMatch {
-                start: byte_start,
-                end: block_end,
-                matched_text: matched.to_string(),
-                line_number: i + 1,
-                context_preview: preview,
-            });
+            let m = LocationMatch::new(content, byte_start, block_end, "definition", 1.0, 0);
+            if contains_identifier(&line_lower, &name) {
+                exact.push(m);
+            } else {
+                partial.push(m);
+            }
         }
     }
 
-    None
+    if exact.is_empty() { partial } else { exact }
+}
+
+/// Whether `name` appears in `line` as a whole identifier.
+fn contains_identifier(line: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(name).any(|(i, _)| {
+        !line[..i].chars().next_back().is_some_and(is_ident)
+            && !line[i + name.len()..].chars().next().is_some_and(is_ident)
+    })
 }
 
 /// Extract a likely identifier name from a natural language pattern.
@@ -361,49 +555,129 @@ fn find_block_end(content: &str, start: usize) -> usize {
     }
 }
 
-/// Fuzzy match by finding the line with highest similarity to the pattern.
-/// Uses both bigram similarity and word containment for better matching.
-fn find_by_fuzzy_match(content: &str, pattern: &str) -> Option<LocationMatch> {
-    let pattern_lower = pattern.to_lowercase();
-    let pattern_words: Vec<&str> = pattern_lower.split_whitespace().collect();
-    let mut best_score = 0.0_f64;
-    let mut best_line_idx = None;
+/// Fuzzy match by scoring every run of lines as long as the pattern.
+/// Uses both bigram similarity and word containment on whitespace-normalized
+/// text, so indentation drift alone still scores 1. Runs scoring within
+/// `AMBIGUITY_MARGIN` of the best are ambiguous; when nothing reaches the
+/// threshold, the closest run is reported with a diff against the pattern.
+fn find_by_fuzzy_match(
+    content: &str,
+    pattern: &str,
+    anchor: Option<Anchor>,
+) -> Result<LocationMatch, String> {
+    let mut pattern_lines: Vec<String> = pattern.lines().map(normalize_line).collect();
+    while pattern_lines.last().is_some_and(|l| l.is_empty()) {
+        pattern_lines.pop();
+    }
+    while pattern_lines.first().is_some_and(|l| l.is_empty()) {
+        pattern_lines.remove(0);
+    }
+    let size = pattern_lines.len().max(1);
+    let pattern_text = pattern_lines.join("\n");
+    let pattern_words: Vec<&str> = pattern_text.split_whitespace().collect();
+    let threshold = if size == 1 {
+        LINE_MATCH_THRESHOLD
+    } else {
+        BLOCK_MATCH_THRESHOLD
+    };
 
-    for (i, line) in content.lines().enumerate() {
-        let line_lower = line.to_lowercase();
-        let line_trimmed = line_lower.trim();
-        if line_trimmed.is_empty() {
+    let spans = line_spans(content);
+    let lines: Vec<String> = spans
+        .iter()
+        .map(|&(start, end)| normalize_line(&content[start..end]))
+        .collect();
+
+    let mut scored: Vec<(f64, usize)> = Vec::new();
+    for (i, window) in lines.windows(size).enumerate() {
+        if window[0].is_empty() || !anchor.is_none_or(|a| a.allows(i + 1)) {
             continue;
         }
+        let text = window.join("\n");
 
         // Combined score: bigram similarity + word containment bonus
-        let bigram_score = similarity_score(line_trimmed, &pattern_lower);
-        let word_score = pattern_words
-            .iter()
-            .filter(|w| line_trimmed.contains(**w))
-            .count() as f64
+        let bigram_score = similarity_score(&text, &pattern_text);
+        let word_score = pattern_words.iter().filter(|w| text.contains(**w)).count() as f64
             / pattern_words.len().max(1) as f64;
 
-        let score = (bigram_score + word_score) / 2.0;
-        if score > best_score && score > 0.25 {
-            best_score = score;
-            best_line_idx = Some(i);
+        scored.push(((bigram_score + word_score) / 2.0, i));
+    }
+    // Best first; equal scores stay in file order.
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let to_match = |(score, i): (f64, usize)| {
+        LocationMatch::new(
+            content,
+            spans[i].0,
+            spans[i + size - 1].1,
+            "fuzzy",
+            score,
+            2,
+        )
+    };
+
+    let best = match scored.first() {
+        Some(&(score, _)) if score > 0.0 => score,
+        _ => {
+            return Err(format!(
+                "Could not locate '{}' in the {}. Try using exact text, a line number (e.g., 'line 42'), or a function name.",
+                truncate(pattern, 80),
+                if anchor.is_some() {
+                    "anchored part of the file"
+                } else {
+                    "file"
+                }
+            ));
+        }
+    };
+
+    if best < threshold {
+        let closest = to_match(scored[0]);
+        return Err(format!(
+            "Could not locate '{}' confidently. The closest match is {} with confidence {:.2}, \
+             below the {:.2} threshold. How the file differs from the search text:\n{}",
+            truncate(pattern, 80),
+            closest.lines_label(),
+            closest.confidence,
+            threshold,
+            drift_diff(pattern, &closest)
+        ));
+    }
+
+    // Keep the best of any overlapping runs, then every run close to the best.
+    let mut contenders: Vec<(f64, usize)> = Vec::new();
+    for &(score, i) in &scored {
+        if score < threshold || best - score > AMBIGUITY_MARGIN {
+            break;
+        }
+        if contenders.iter().all(|&(_, j)| i.abs_diff(j) >= size) {
+            contenders.push((score, i));
         }
     }
 
-    let line_idx = best_line_idx?;
-    let byte_start: usize = content.lines().take(line_idx).map(|l| l.len() + 1).sum();
-    let line_text = content.lines().nth(line_idx)?;
-    let byte_end = byte_start + line_text.len();
-    let preview = extract_context(content, byte_start, byte_end, 2);
+    choose(
+        pattern,
+        contenders.into_iter().map(to_match).collect(),
+        anchor,
+    )
+}
 
-    Some(LocationMatch {
-        start: byte_start,
-        end: byte_end,
-        matched_text: line_text.to_string(),
-        line_number: line_idx + 1,
-        context_preview: preview,
-    })
+/// Lowercase a line and collapse its whitespace, for fuzzy comparison.
+fn normalize_line(line: &str) -> String {
+    line.to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Unified diff from the search text to the file text it was closest to.
+fn drift_diff(pattern: &str, closest: &LocationMatch) -> String {
+    let search = format!("{}\n", pattern.trim_end_matches('\n'));
+    let actual = format!("{}\n", closest.matched_text.trim_end_matches('\n'));
+    let diff = TextDiff::from_lines(&search, &actual);
+    diff.unified_diff()
+        .context_radius(3)
+        .header("search text", &format!("file {}", closest.lines_label()))
+        .to_string()
 }
 
 /// Simple similarity score between two strings (Jaccard on character bigrams).
@@ -427,6 +701,27 @@ fn similarity_score(a: &str, b: &str) -> f64 {
     intersection / union
 }
 
+/// Byte span of each line, excluding its line terminator.
+fn line_spans(content: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for line in content.split_inclusive('\n') {
+        let text = line.trim_end_matches('\n').trim_end_matches('\r');
+        spans.push((start, start + text.len()));
+        start += line.len();
+    }
+    spans
+}
+
+/// 1-based line number of the byte at `offset`.
+fn line_at(content: &str, offset: usize) -> usize {
+    content.as_bytes()[..offset.min(content.len())]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count()
+        + 1
+}
+
 /// Extract context lines around a byte range.
 fn extract_context(content: &str, start: usize, end: usize, context_lines: usize) -> String {
     let lines: Vec<&str> = content.lines().collect();
@@ -578,8 +873,9 @@ impl Tool for SmartEditTool {
     fn description(&self) -> &str {
         "Smart code editor that accepts fuzzy location descriptions (function names, \
          line numbers, search patterns) and edit types (replace, insert_after, \
-         insert_before, delete). Creates an auto-checkpoint before writing and \
-         returns a unified diff preview."
+         insert_before, delete). Refuses locations that match several places \
+         equally well and lists them; pass an anchor to pick one. Creates an \
+         auto-checkpoint before writing and returns a unified diff preview."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -605,6 +901,12 @@ impl Tool for SmartEditTool {
                     "type": "string",
                     "description": "The new text (required for replace, insert_after, insert_before; \
                         omit for delete)"
+                },
+                "anchor": {
+                    "type": "string",
+                    "description": "Optional. Narrows where 'location' may match: a unique string \
+                        near the target (the closest match wins) or a line range such as \
+                        'lines 10-20'. Use it when the location matches several places."
                 }
             },
             "required": ["path", "location", "edit_type"]
@@ -668,21 +970,41 @@ impl Tool for SmartEditTool {
                 })?;
 
         // Find the location
-        let location =
-            find_location(&content, location_str).map_err(|e| ToolError::ExecutionFailed {
+        let anchor = match args["anchor"].as_str() {
+            Some(anchor) => Some(Anchor::resolve(&content, anchor).map_err(|e| {
+                ToolError::InvalidArguments {
+                    name: "smart_edit".into(),
+                    reason: e,
+                }
+            })?),
+            None => None,
+        };
+        let location = find_location(&content, location_str, anchor).map_err(|e| {
+            ToolError::ExecutionFailed {
                 name: "smart_edit".into(),
                 message: e,
-            })?;
+            }
+        })?;
 
         debug!(
-            "smart_edit: matched at line {} ({} bytes)",
+            "smart_edit: {} match at line {} ({} bytes, confidence {:.2})",
+            location.strategy,
             location.line_number,
-            location.matched_text.len()
+            location.matched_text.len(),
+            location.confidence
         );
 
         // Apply the edit
         let new_content = apply_edit(&content, &location, edit_type, new_text);
 
+        let mut pre_write_note = None;
+        if let Some(check) = &self.pre_write {
+            match check.review(&path, path_str, &new_content).await {
+                PreWriteOutcome::Reject { prompt } => return Ok(ToolOutput::error(prompt)),
+                PreWriteOutcome::Write { note } => pre_write_note = note,
+            }
+        }
+
         // Generate diff
         let diff = generate_diff(path_str, &content, &new_content);
 
@@ -718,12 +1040,31 @@ impl Tool for SmartEditTool {
             ""
         };
 
-        let summary = format!(
-            "Edited '{}': {} at line {}{}\n\nDiff:\n{}",
-            path_str, edit_desc, location.line_number, checkpoint_note, diff
+        let mut summary = format!(
+            "Edited '{}': {} at {} ({} match, confidence {:.2}){}\n\nDiff:\n{}",
+            path_str,
+            edit_desc,
+            location.lines_label(),
+            location.strategy,
+            location.confidence,
+            checkpoint_note,
+            diff
         );
+        if let Some(note) = pre_write_note {
+            summary.push('\n');
+            summary.push_str(&note);
+        }
 
         let mut output = ToolOutput::text(summary);
+        output.metadata.insert(
+            "match".into(),
+            serde_json::json!({
+                "strategy": location.strategy,
+                "confidence": location.confidence,
+                "start_line": location.line_number,
+                "end_line": location.end_line,
+            }),
+        );
         output.artifacts.push(Artifact::FileModified {
             path: PathBuf::from(path_str),
             diff,
@@ -746,7 +1087,7 @@ mod tests {
     #[test]
     fn test_find_location_exact() {
         let content = "fn main() {\n    println!(\"hello\");\n}\n";
-        let loc = find_location(content, "println!(\"hello\")").unwrap();
+        let loc = find_location(content, "println!(\"hello\")", None).unwrap();
         assert_eq!(loc.line_number, 2);
         assert_eq!(loc.matched_text, "println!(\"hello\")");
     }
@@ -754,7 +1095,7 @@ mod tests {
     #[test]
     fn test_find_location_line_number() {
         let content = "line one\nline two\nline three\n";
-        let loc = find_location(content, "line 2").unwrap();
+        let loc = find_location(content, "line 2", None).unwrap();
         assert_eq!(loc.line_number, 2);
         assert!(loc.matched_text.contains("line two"));
     }
@@ -762,7 +1103,7 @@ mod tests {
     #[test]
     fn test_find_location_line_range() {
         let content = "a\nb\nc\nd\ne\n";
-        let loc = find_location(content, "lines 2-4").unwrap();
+        let loc = find_location(content, "lines 2-4", None).unwrap();
         assert_eq!(loc.line_number, 2);
         assert!(loc.matched_text.contains('b'));
         assert!(loc.matched_text.contains('c'));
@@ -772,7 +1113,7 @@ mod tests {
     #[test]
     fn test_find_location_function_pattern() {
         let content = "use std::io;\n\nfn handle_request(req: Request) {\n    process(req);\n}\n\nfn main() {}\n";
-        let loc = find_location(content, "fn handle_request").unwrap();
+        let loc = find_location(content, "fn handle_request", None).unwrap();
         assert_eq!(loc.line_number, 3);
         assert!(loc.matched_text.contains("handle_request"));
     }
@@ -780,21 +1121,137 @@ mod tests {
     #[test]
     fn test_find_location_fuzzy() {
         let content = "struct Config {\n    timeout: u64,\n    retries: usize,\n}\n";
-        let loc = find_location(content, "timeout field").unwrap();
+        let loc = find_location(content, "timeout field", None).unwrap();
         assert!(loc.matched_text.contains("timeout"));
     }
 
     #[test]
     fn test_find_location_not_found() {
         let content = "hello world\n";
-        let result = find_location(content, "nonexistent_xyz_123");
+        let result = find_location(content, "nonexistent_xyz_123", None);
         assert!(result.is_err());
     }
 
+    const DUPLICATED: &str = "fn total(items: &[u32]) -> u32 {
+    let mut sum = 0;
+    for i in items {
+        sum += i;
+    }
+    sum
+}
+
+fn total_again(items: &[u32]) -> u32 {
+    let mut sum = 0;
+    for i in items {
+        sum += i;
+    }
+    sum
+}
+";
+
+    #[test]
+    fn test_find_location_duplicated_function_is_ambiguous() {
+        // Exact text that occurs twice.
+        let err = find_location(
+            DUPLICATED,
+            "    let mut sum = 0;\n    for i in items {",
+            None,
+        )
+        .unwrap_err();
+        assert!(err.contains("matches 2 locations"));
+        assert!(err.contains("Candidate 1: lines 2-3 (exact match"));
+        assert!(err.contains("Candidate 2: lines 10-11 (exact match"));
+
+        // Drifted search text that fits both copies equally well.
+        let drifted = "let mut sum = 0;\nfor item in items {\n    sum += item;\n}";
+        let err = find_location(DUPLICATED, drifted, None).unwrap_err();
+        assert!(err.contains("matches 2 locations"));
+        assert!(err.contains("Candidate 1: lines 2-5 (fuzzy match"));
+        assert!(err.contains("Candidate 2: lines 10-13 (fuzzy match"));
+        assert!(err.contains("  10 |     let mut sum = 0;"));
+    }
+
+    #[test]
+    fn test_find_location_anchor_disambiguates() {
+        let drifted = "let mut sum = 0;\nfor item in items {\n    sum += item;\n}";
+
+        let near = Anchor::resolve(DUPLICATED, "fn total_again").unwrap();
+        assert_eq!(near, Anchor::Near(9));
+        let loc = find_location(DUPLICATED, drifted, Some(near)).unwrap();
+        assert_eq!((loc.line_number, loc.end_line), (10, 13));
+        assert_eq!(loc.strategy, "fuzzy");
+        assert!(loc.confidence > 0.8 && loc.confidence < 1.0);
+
+        let lines = Anchor::resolve(DUPLICATED, "lines 1-7").unwrap();
+        let loc = find_location(DUPLICATED, drifted, Some(lines)).unwrap();
+        assert_eq!((loc.line_number, loc.end_line), (2, 5));
+
+        let loc = find_location(DUPLICATED, "let mut sum = 0;", Some(near)).unwrap();
+        assert_eq!((loc.line_number, loc.strategy), (10, "exact"));
+    }
+
+    #[test]
+    fn test_anchor_resolve() {
+        assert_eq!(Anchor::resolve(DUPLICATED, "42"), Ok(Anchor::Lines(42, 42)));
+        assert_eq!(
+            Anchor::resolve(DUPLICATED, "10-20"),
+            Ok(Anchor::Lines(10, 20))
+        );
+        assert_eq!(
+            Anchor::resolve(DUPLICATED, "line 3"),
+            Ok(Anchor::Lines(3, 3))
+        );
+        assert!(Anchor::resolve(DUPLICATED, "20-10").is_err());
+        assert!(Anchor::resolve(DUPLICATED, "0").is_err());
+        assert!(Anchor::resolve(DUPLICATED, "not in the file").is_err());
+        let err = Anchor::resolve(DUPLICATED, "let mut sum").unwrap_err();
+        assert!(err.contains("appears 2 times (lines 2, 10)"));
+    }
+
+    #[test]
+    fn test_find_location_whitespace_drift() {
+        let content =
+            "impl Config {\n    fn timeout(&self) -> u64 {\n        self.timeout\n    }\n}\n";
+        let search = "fn timeout(&self) -> u64 {\n  self.timeout\n}";
+        let loc = find_location(content, search, None).unwrap();
+        assert_eq!((loc.line_number, loc.end_line), (2, 4));
+        assert_eq!(loc.strategy, "fuzzy");
+        assert!((loc.confidence - 1.0).abs() < f64::EPSILON);
+        assert_eq!(
+            loc.matched_text,
+            "    fn timeout(&self) -> u64 {\n        self.timeout\n    }"
+        );
+
+        let single =
+            find_location("fn main() {\n    let x = 1;\n}\n", "let  x =\t1;", None).unwrap();
+        assert_eq!(single.line_number, 2);
+        assert!((single.confidence - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_find_location_below_threshold_reports_diff() {
+        let content = "fn main() {\n    let a = 1;\n    let b = 2;\n}\n";
+        let err = find_location(content, "let alpha = compute();\nlet beta = other();", None)
+            .unwrap_err();
+        assert!(err.contains("Could not locate"));
+        assert!(err.contains("The closest match is lines"));
+        assert!(err.contains("below the 0.60 threshold"));
+        assert!(err.contains("--- search text"));
+        assert!(err.contains("+++ file lines"));
+        assert!(err.contains("-let alpha = compute();"));
+    }
+
+    #[test]
+    fn test_find_location_prefers_whole_identifier() {
+        let content = "fn process_all() {}\n\nfn process() {}\n";
+        let loc = find_location(content, "the process function", None).unwrap();
+        assert_eq!(loc.line_number, 3);
+    }
+
     #[test]
     fn test_apply_edit_replace() {
         let content = "fn old_name() {}\n";
-        let loc = find_location(content, "old_name").unwrap();
+        let loc = find_location(content, "old_name", None).unwrap();
         let result = apply_edit(content, &loc, EditType::Replace, "new_name");
         assert!(result.contains("new_name"));
         assert!(!result.contains("old_name"));
@@ -803,7 +1260,7 @@ mod tests {
     #[test]
     fn test_apply_edit_insert_after() {
         let content = "use std::io;\n\nfn main() {}\n";
-        let loc = find_location(content, "use std::io;").unwrap();
+        let loc = find_location(content, "use std::io;", None).unwrap();
         let result = apply_edit(content, &loc, EditType::InsertAfter, "use std::fs;");
         assert!(result.contains("use std::io;\nuse std::fs;"));
     }
@@ -811,7 +1268,7 @@ mod tests {
     #[test]
     fn test_apply_edit_insert_before() {
         let content = "fn main() {}\n";
-        let loc = find_location(content, "fn main").unwrap();
+        let loc = find_location(content, "fn main", None).unwrap();
         let result = apply_edit(content, &loc, EditType::InsertBefore, "// Entry point\n");
         assert!(result.starts_with("// Entry point\n"));
     }
@@ -819,7 +1276,7 @@ mod tests {
     #[test]
     fn test_apply_edit_delete() {
         let content = "line1\nline2\nline3\n";
-        let loc = find_location(content, "line2").unwrap();
+        let loc = find_location(content, "line2", None).unwrap();
         let result = apply_edit(content, &loc, EditType::Delete, "");
         assert!(!result.contains("line2"));
         assert!(result.contains("line1"));
@@ -949,6 +1406,36 @@ mod tests {
         assert!(!content.contains("old_name"));
     }
 
+    #[tokio::test]
+    async fn test_smart_edit_tool_returns_new_errors_instead_of_writing() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().to_path_buf();
+        fs::write(workspace.join("test.rs"), "fn old_name() {}\n").unwrap();
+
+        let check = Arc::new(crate::lsp::pre_write::marker_check(
+            3,
+            std::time::Duration::ZERO,
+        ));
+        let tool = SmartEditTool::new(workspace.clone()).with_pre_write_check(check);
+
+        let args = serde_json::json!({
+            "path": "test.rs",
+            "location": "old_name",
+            "edit_type": "replace",
+            "new_text": "BROKEN_name"
+        });
+        let result = tool.execute(args).await.unwrap();
+        assert_eq!(
+            result.metadata.get("is_error"),
+            Some(&serde_json::json!(true))
+        );
+        assert!(result.content.contains("BROKEN_name"), "{}", result.content);
+        assert_eq!(
+            fs::read_to_string(workspace.join("test.rs")).unwrap(),
+            "fn old_name() {}\n"
+        );
+    }
+
     #[tokio::test]
     async fn test_smart_edit_tool_execute_delete() {
         let dir = TempDir::new().unwrap();
@@ -1026,6 +1513,41 @@ mod tests {
         assert!(!content.contains("line 2"));
     }
 
+    #[tokio::test]
+    async fn test_smart_edit_tool_refuses_ambiguous_location() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().to_path_buf();
+        fs::write(workspace.join("lib.rs"), DUPLICATED).unwrap();
+        let tool = SmartEditTool::new(workspace.clone());
+
+        let mut args = serde_json::json!({
+            "path": "lib.rs",
+            "location": "let mut sum = 0;",
+            "edit_type": "replace",
+            "new_text": "let mut sum = 1;"
+        });
+        let err = tool.execute(args.clone()).await.unwrap_err();
+        assert!(err.to_string().contains("matches 2 locations"));
+        assert_eq!(
+            fs::read_to_string(workspace.join("lib.rs")).unwrap(),
+            DUPLICATED
+        );
+
+        args["anchor"] = "fn total_again".into();
+        let result = tool.execute(args).await.unwrap();
+        assert!(
+            result
+                .content
+                .contains("replaced at line 10 (exact match, confidence 1.00)")
+        );
+        assert_eq!(result.metadata["match"]["start_line"], 10);
+        assert_eq!(result.metadata["match"]["confidence"], 1.0);
+
+        let content = fs::read_to_string(workspace.join("lib.rs")).unwrap();
+        assert!(content.contains("fn total(items: &[u32]) -> u32 {\n    let mut sum = 0;"));
+        assert!(content.contains("fn total_again(items: &[u32]) -> u32 {\n    let mut sum = 1;"));
+    }
+
     #[tokio::test]
     async fn test_smart_edit_tool_missing_new_text() {
         let dir = TempDir::new().unwrap();
@@ -0,0 +1,127 @@
+//! Security advisories and which versions they affect.
+//!
+//! Advisories come from OSV when the network is available and otherwise from
+//! a local RustSec advisory database (the clone `cargo audit` keeps under
+//! `$CARGO_HOME/advisory-db`), so Rust packages keep advisory coverage
+//! offline.
+
+use std::path::{Path, PathBuf};
+
+use super::version::{Ecosystem, Requirement, Version};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Affected {
+    /// OSV-style `[introduced, fixed)` ranges plus explicitly listed versions.
+    Ranges {
+        ranges: Vec<(Option<Version>, Option<Version>)>,
+        versions: Vec<Version>,
+    },
+    /// RustSec-style requirements: affected unless patched or unaffected.
+    Requirements {
+        patched: Vec<Requirement>,
+        unaffected: Vec<Requirement>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Advisory {
+    pub id: String,
+    pub summary: String,
+    pub affected: Affected,
+}
+
+impl Advisory {
+    pub fn affects(&self, version: &Version) -> bool {
+        match &self.affected {
+            Affected::Ranges { ranges, versions } => {
+                versions.contains(version)
+                    || ranges.iter().any(|(introduced, fixed)| {
+                        introduced.as_ref().is_none_or(|i| version >= i)
+                            && fixed.as_ref().is_none_or(|f| version < f)
+                    })
+            }
+            Affected::Requirements {
+                patched,
+                unaffected,
+            } => !patched.iter().chain(unaffected).any(|r| r.matches(version)),
+        }
+    }
+}
+
+/// Location of the local RustSec database, if one has been cloned.
+pub fn local_advisory_db() -> Option<PathBuf> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cargo")))?;
+    let db = cargo_home.join("advisory-db");
+    db.join("crates").is_dir().then_some(db)
+}
+
+/// Advisories for `package` in a RustSec database checkout.
+pub fn load_rustsec(db: &Path, package: &str) -> Vec<Advisory> {
+    let Ok(entries) = std::fs::read_dir(db.join("crates").join(package)) else {
+        return Vec::new();
+    };
+    let mut advisories: Vec<Advisory> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|x| x == "md"))
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .filter_map(|text| parse_rustsec(&text))
+        .collect();
+    advisories.sort_by(|a, b| a.id.cmp(&b.id));
+    advisories
+}
+
+/// Parse a RustSec advisory: a fenced TOML front matter block followed by a
+/// Markdown body whose first heading is the summary.
+pub fn parse_rustsec(text: &str) -> Option<Advisory> {
+    let rest = text.trim_start().strip_prefix("```toml")?;
+    let (front, body) = rest.split_once("```")?;
+    let meta: toml::Value = toml::from_str(front).ok()?;
+    let advisory = meta.get("advisory")?;
+    if advisory.get("withdrawn").is_some() {
+        return None;
+    }
+    let requirements = |key: &str| -> Vec<Requirement> {
+        meta.get("versions")
+            .and_then(|v| v.get(key))
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|r| r.as_str())
+            .filter_map(|r| Requirement::parse(Ecosystem::Cargo, r))
+            .collect()
+    };
+    let summary = body
+        .lines()
+        .find_map(|l| l.strip_prefix("# "))
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    Some(Advisory {
+        id: advisory.get("id")?.as_str()?.to_string(),
+        summary,
+        affected: Affected::Requirements {
+            patched: requirements("patched"),
+            unaffected: requirements("unaffected"),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rustsec_advisory_affects() {
+        let text = "```toml\n[advisory]\nid = \"RUSTSEC-2024-0001\"\npackage = \"demo\"\n\n\
+                    [versions]\npatched = [\">= 1.4.2\"]\nunaffected = [\"< 1.0.0\"]\n```\n\n\
+                    # Out-of-bounds read in parser\n\nDetails.\n";
+        let advisory = parse_rustsec(text).unwrap();
+        assert_eq!(advisory.id, "RUSTSEC-2024-0001");
+        assert_eq!(advisory.summary, "Out-of-bounds read in parser");
+        assert!(advisory.affects(&Version::new(1, 4, 1)));
+        assert!(!advisory.affects(&Version::new(1, 4, 2)));
+        assert!(!advisory.affects(&Version::new(0, 9, 0)));
+    }
+}
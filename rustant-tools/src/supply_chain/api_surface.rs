@@ -0,0 +1,536 @@
+//! Public API surface of a Rust crate, read from its source.
+//!
+//! This is a line-oriented scan, not a compiler: it collects `pub` items
+//! (functions, types, traits, consts, re-exports), `pub` methods of inherent
+//! impls, trait methods, `pub` struct fields and enum variants, keyed by
+//! module path, and compares signatures textually between two versions.
+//! Macro-generated items and `#[cfg]` variants are invisible to it, so a
+//! clean diff means "nothing obvious", not "no breaking change".
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::version::Version;
+
+/// Where downloaded crate sources are unpacked, relative to the workspace.
+pub const SOURCE_CACHE_DIR: &str = ".rustant/supply_chain/src";
+
+/// Public items by path (`module::Type::method`) with normalized signatures.
+pub type ApiSurface = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApiDiff {
+    pub removed: Vec<String>,
+    /// `(path, old signature, new signature)`.
+    pub changed: Vec<(String, String, String)>,
+    pub added: Vec<String>,
+}
+
+impl ApiDiff {
+    pub fn between(old: &ApiSurface, new: &ApiSurface) -> Self {
+        let mut diff = Self::default();
+        for (path, old_sig) in old {
+            match new.get(path) {
+                None => diff.removed.push(path.clone()),
+                Some(new_sig) if new_sig != old_sig => {
+                    diff.changed
+                        .push((path.clone(), old_sig.clone(), new_sig.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        diff.added = new
+            .keys()
+            .filter(|path| !old.contains_key(*path))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty() || !self.changed.is_empty()
+    }
+}
+
+/// Unpacked source of `name` at `version` already on disk: `vendor/`, the
+/// cargo registry cache, or a previous download.
+pub fn locate_cached_source(workspace: &Path, name: &str, version: &Version) -> Option<PathBuf> {
+    let dir_name = format!("{name}-{version}");
+    let mut candidates = vec![
+        workspace.join("vendor").join(&dir_name),
+        workspace.join(SOURCE_CACHE_DIR).join(&dir_name),
+    ];
+    // `cargo vendor` drops the version suffix for the newest copy.
+    let vendored = workspace.join("vendor").join(name);
+    if manifest_version(&vendored).as_ref() == Some(version) {
+        candidates.push(vendored);
+    }
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cargo")));
+    if let Some(index_dirs) =
+        cargo_home.and_then(|home| std::fs::read_dir(home.join("registry").join("src")).ok())
+    {
+        candidates.extend(
+            index_dirs
+                .filter_map(|e| e.ok())
+                .map(|e| e.path().join(&dir_name)),
+        );
+    }
+    candidates
+        .into_iter()
+        .find(|dir| dir.join("Cargo.toml").is_file())
+}
+
+fn manifest_version(dir: &Path) -> Option<Version> {
+    let text = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    let manifest: toml::Value = toml::from_str(&text).ok()?;
+    Version::parse(manifest.get("package")?.get("version")?.as_str()?)
+}
+
+/// Scan every `.rs` file under `src/` of a crate.
+pub fn scan_crate(root: &Path) -> ApiSurface {
+    let src = root.join("src");
+    let mut surface = ApiSurface::new();
+    for entry in WalkDir::new(&src)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|x| x == "rs"))
+    {
+        let Ok(relative) = entry.path().strip_prefix(&src) else {
+            continue;
+        };
+        if relative.starts_with("bin") || relative.ends_with("main.rs") {
+            continue;
+        }
+        if let Ok(text) = std::fs::read_to_string(entry.path()) {
+            scan_source(&module_path(relative), &text, &mut surface);
+        }
+    }
+    surface
+}
+
+/// Module path for a file relative to `src/`: `lib.rs` is the crate root,
+/// `a/mod.rs` and `a.rs` are `a`.
+fn module_path(relative: &Path) -> String {
+    let mut parts: Vec<String> = relative
+        .with_extension("")
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    if matches!(parts.last().map(String::as_str), Some("lib" | "mod")) {
+        parts.pop();
+    }
+    parts.join("::")
+}
+
+#[derive(Debug)]
+enum Scope {
+    /// A module; items inside a private one are not public API.
+    Module {
+        name: String,
+        public: bool,
+    },
+    Impl(String),
+    Trait(String),
+    Struct(String),
+    Enum(String),
+    /// Function bodies and anything else whose contents are not API.
+    Opaque,
+}
+
+/// Scan one file's source, adding its public items to `surface`.
+pub fn scan_source(module: &str, text: &str, surface: &mut ApiSurface) {
+    let mut scopes: Vec<(Scope, i32)> = Vec::new();
+    let mut depth = 0i32;
+    let mut pending = String::new();
+    let mut in_block_comment = false;
+
+    for raw in text.lines() {
+        let line = strip_line(raw, &mut in_block_comment);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("#[") || line.starts_with("#![") {
+            continue;
+        }
+        let inside_opaque = scopes.iter().any(|(s, _)| matches!(s, Scope::Opaque))
+            || scopes
+                .iter()
+                .any(|(s, _)| matches!(s, Scope::Module { public: false, .. }));
+
+        // Collect multi-line declarations until their body or terminator.
+        if !pending.is_empty() || (!inside_opaque && starts_item(line, &scopes)) {
+            pending.push(' ');
+            pending.push_str(line);
+            let trimmed = pending.trim_end();
+            // Fields and variants end at a comma or the closing brace.
+            let in_type = matches!(scopes.last(), Some((Scope::Struct(_) | Scope::Enum(_), _)));
+            let done = trimmed.contains('{')
+                || trimmed.ends_with(';')
+                || (in_type && (trimmed.ends_with(',') || trimmed.contains('}')));
+            if !done && pending.len() < 1000 {
+                continue;
+            }
+            let declaration = std::mem::take(&mut pending);
+            let opens = declaration.matches('{').count() as i32;
+            let closes = declaration.matches('}').count() as i32;
+            let scope = record(module, declaration.trim(), &scopes, surface);
+            if opens > closes {
+                scopes.push((scope, depth + 1));
+            }
+            depth += opens - closes;
+        } else {
+            let opens = line.matches('{').count() as i32;
+            let closes = line.matches('}').count() as i32;
+            if opens > closes {
+                let scope = if inside_opaque {
+                    Scope::Opaque
+                } else {
+                    open_scope(line)
+                };
+                scopes.push((scope, depth + 1));
+            }
+            depth += opens - closes;
+        }
+        while scopes.last().is_some_and(|(_, d)| *d > depth) {
+            scopes.pop();
+        }
+    }
+}
+
+/// Whether `line` starts an item worth recording in the current scope.
+fn starts_item(line: &str, scopes: &[(Scope, i32)]) -> bool {
+    match scopes.last().map(|(s, _)| s) {
+        Some(Scope::Trait(_)) => {
+            line.starts_with("fn ")
+                || line.starts_with("async fn ")
+                || line.starts_with("unsafe fn ")
+        }
+        Some(Scope::Struct(_)) => line.starts_with("pub ") && !line.starts_with("pub("),
+        Some(Scope::Enum(_)) => line.starts_with(|c: char| c.is_ascii_uppercase()),
+        _ => {
+            line.starts_with("pub ") || line.starts_with("impl") || line.starts_with("unsafe impl")
+        }
+    }
+}
+
+/// Record a declaration and return the scope its body (if any) opens.
+fn record(
+    module: &str,
+    declaration: &str,
+    scopes: &[(Scope, i32)],
+    surface: &mut ApiSurface,
+) -> Scope {
+    let signature = normalize(declaration);
+    let mut path: Vec<String> = Vec::new();
+    if !module.is_empty() {
+        path.push(module.to_string());
+    }
+    for (scope, _) in scopes {
+        if let Scope::Module { name, .. } = scope {
+            path.push(name.clone());
+        }
+    }
+    let parent = scopes.last().map(|(s, _)| s);
+    if let Some(Scope::Impl(ty) | Scope::Trait(ty) | Scope::Struct(ty) | Scope::Enum(ty)) = parent {
+        path.push(ty.clone());
+    }
+
+    if declaration.starts_with("impl") || declaration.starts_with("unsafe impl") {
+        return open_scope(declaration);
+    }
+    let (kind, name) = item_kind_and_name(declaration);
+    if kind == "field" || kind == "variant" {
+        path.push(name);
+        let signature = signature
+            .trim_end_matches('}')
+            .trim_end()
+            .trim_end_matches(',');
+        surface.insert(path.join("::"), body_free(signature));
+        return Scope::Opaque;
+    }
+    if matches!(parent, Some(Scope::Impl(ty)) if ty.contains(" for ")) {
+        // Methods of trait impls are the trait's API, not the type's.
+        return Scope::Opaque;
+    }
+    if name.is_empty() {
+        return Scope::Opaque;
+    }
+    path.push(name.clone());
+    let key = path.join("::");
+    match kind {
+        "mod" => {
+            if declaration.contains('{') {
+                return Scope::Module { name, public: true };
+            }
+            Scope::Opaque
+        }
+        "use" => {
+            surface.insert(key, signature);
+            Scope::Opaque
+        }
+        "struct" | "union" => {
+            surface.insert(key, body_free(&signature));
+            Scope::Struct(name)
+        }
+        "enum" => {
+            surface.insert(key, body_free(&signature));
+            Scope::Enum(name)
+        }
+        "trait" => {
+            surface.insert(key, body_free(&signature));
+            Scope::Trait(name)
+        }
+        _ => {
+            surface.insert(key, body_free(&signature));
+            Scope::Opaque
+        }
+    }
+}
+
+/// The scope opened by a brace on a line that is not a recorded item.
+fn open_scope(line: &str) -> Scope {
+    let line = line.trim();
+    if line.starts_with("impl") || line.starts_with("unsafe impl") {
+        let header = line.split('{').next().unwrap_or_default();
+        let header = header.split(" where ").next().unwrap_or(header);
+        let header = header
+            .trim_start_matches("unsafe ")
+            .trim_start_matches("impl");
+        // Skip the generic parameter list of the impl itself.
+        let header = if header.starts_with('<') {
+            let mut depth = 0;
+            let end = header
+                .char_indices()
+                .find(|(_, c)| {
+                    match c {
+                        '<' => depth += 1,
+                        '>' => depth -= 1,
+                        _ => {}
+                    }
+                    depth == 0
+                })
+                .map(|(i, _)| i + 1)
+                .unwrap_or(0);
+            &header[end..]
+        } else {
+            header
+        };
+        let header = header.trim();
+        return match header.split_once(" for ") {
+            Some((trait_name, ty)) => {
+                Scope::Impl(format!("{} for {}", base_name(trait_name), base_name(ty)))
+            }
+            None => Scope::Impl(base_name(header)),
+        };
+    }
+    if let Some(rest) = line.strip_prefix("mod ") {
+        let name = rest
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .next()
+            .unwrap_or_default();
+        return Scope::Module {
+            name: name.to_string(),
+            public: false,
+        };
+    }
+    Scope::Opaque
+}
+
+/// `Foo` for `Foo<T>`, `crate::a::Foo<'a>`.
+fn base_name(ty: &str) -> String {
+    let ty = ty.trim().split('<').next().unwrap_or_default();
+    ty.rsplit("::").next().unwrap_or(ty).trim().to_string()
+}
+
+fn item_kind_and_name(declaration: &str) -> (&'static str, String) {
+    let mut words = declaration
+        .split(|c: char| {
+            c.is_whitespace()
+                || c == '('
+                || c == '<'
+                || c == ':'
+                || c == '{'
+                || c == ';'
+                || c == ','
+                || c == '='
+        })
+        .filter(|w| !w.is_empty())
+        .peekable();
+    if declaration.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return ("variant", words.next().unwrap_or_default().to_string());
+    }
+    if words.peek() == Some(&"pub") {
+        words.next();
+    }
+    while let Some(word) = words.next() {
+        let kind = match word {
+            "fn" => "fn",
+            "struct" => "struct",
+            "union" => "union",
+            "enum" => "enum",
+            "trait" => "trait",
+            "type" => "type",
+            "const" if words.peek() != Some(&"fn") && words.peek() != Some(&"unsafe") => "const",
+            "static" => "static",
+            "mod" => "mod",
+            "macro" => "macro",
+            "use" => {
+                let path = declaration
+                    .split_once("use ")
+                    .map(|(_, p)| p.trim_end_matches(';').trim())
+                    .unwrap_or_default();
+                return ("use", path.to_string());
+            }
+            "async" | "unsafe" | "extern" | "const" | "\"C\"" | "mut" | "auto" => continue,
+            field => return ("field", field.to_string()),
+        };
+        return (kind, words.next().unwrap_or_default().to_string());
+    }
+    ("", String::new())
+}
+
+/// Drop a trailing body or terminator and collapse whitespace.
+fn body_free(signature: &str) -> String {
+    signature
+        .split('{')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches([';', ','])
+        .trim()
+        .to_string()
+}
+
+fn normalize(declaration: &str) -> String {
+    let collapsed = declaration.split_whitespace().collect::<Vec<_>>().join(" ");
+    collapsed
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace(" ,", ",")
+        .trim_end_matches([';', ','])
+        .to_string()
+}
+
+/// Remove comments and the contents of string and char literals so braces
+/// inside them are not counted.
+fn strip_line(line: &str, in_block_comment: &mut bool) -> String {
+    let mut out = String::with_capacity(line.len());
+    let chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+    let mut in_string = false;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if *in_block_comment {
+            if c == '*' && next == Some('/') {
+                *in_block_comment = false;
+                i += 1;
+            }
+        } else if in_string {
+            if c == '\\' {
+                i += 1;
+            } else if c == '"' {
+                in_string = false;
+                out.push('"');
+            }
+        } else if c == '/' && next == Some('/') {
+            break;
+        } else if c == '/' && next == Some('*') {
+            *in_block_comment = true;
+            i += 1;
+        } else if c == '"' {
+            in_string = true;
+            out.push('"');
+        } else if c == '\'' && chars.get(i + 2) == Some(&'\'') {
+            out.push_str("' '");
+            i += 2;
+        } else {
+            out.push(c);
+        }
+        i += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn surface(text: &str) -> ApiSurface {
+        let mut surface = ApiSurface::new();
+        scan_source("", text, &mut surface);
+        surface
+    }
+
+    #[test]
+    fn test_scan_and_diff_public_items() {
+        let old = surface(
+            r#"
+pub struct Client {
+    pub timeout: u64,
+    inner: Inner,
+}
+
+impl Client {
+    pub fn new(url: &str) -> Self {
+        let s = "{";
+        todo!()
+    }
+    pub fn get(&self, path: &str) -> Response { todo!() }
+    fn helper(&self) {}
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {}
+}
+
+pub enum Mode {
+    Fast,
+    Safe(u8),
+}
+
+mod private {
+    pub fn hidden() {}
+}
+
+#[cfg(test)]
+mod tests {
+    pub fn also_hidden() {}
+}
+"#,
+        );
+        assert_eq!(
+            old.keys().map(String::as_str).collect::<Vec<_>>(),
+            vec![
+                "Client",
+                "Client::get",
+                "Client::new",
+                "Client::timeout",
+                "Mode",
+                "Mode::Fast",
+                "Mode::Safe",
+            ]
+        );
+
+        let new = surface(
+            r#"
+pub struct Client {
+    pub timeout: u64,
+}
+impl Client {
+    pub fn new(url: &str, retries: u32) -> Self { todo!() }
+    pub fn post(&self) {}
+}
+pub enum Mode {
+    Fast
+}
+"#,
+        );
+        let diff = ApiDiff::between(&old, &new);
+        assert_eq!(diff.removed, vec!["Client::get", "Mode::Safe"]);
+        assert_eq!(diff.changed.len(), 1, "{:?}", diff.changed);
+        assert_eq!(diff.changed[0].0, "Client::new");
+        assert_eq!(diff.added, vec!["Client::post"]);
+        assert!(diff.is_breaking());
+    }
+}
@@ -0,0 +1,467 @@
+//! The workspace dependency graph: locked versions and the requirements
+//! that constrain them.
+//!
+//! Reads `Cargo.lock` plus the workspace manifests, npm's `package-lock.json`
+//! (lockfile v2/v3 `packages` map) and pip's `requirements.txt`. Requirements
+//! declared by locked Cargo dependencies are read from their manifests in the
+//! local cargo cache or `vendor/` when present, and from the registry
+//! otherwise.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::api_surface::locate_cached_source;
+use super::registry::RegistryClient;
+use super::version::{Ecosystem, Requirement, Version};
+
+/// A requirement some package places on a dependency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constraint {
+    pub package: String,
+    /// The requirement as written.
+    pub requirement: String,
+    /// Who declares it: a workspace manifest or `name version`.
+    pub required_by: String,
+    /// Declared by the workspace itself rather than by a dependency.
+    pub direct: bool,
+}
+
+impl Constraint {
+    pub fn parsed(&self, ecosystem: Ecosystem) -> Option<Requirement> {
+        Requirement::parse(ecosystem, &self.requirement)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyGraph {
+    pub ecosystem: Ecosystem,
+    /// Locked versions by package name (several for duplicated crates).
+    pub locked: BTreeMap<String, Vec<Version>>,
+    /// Locked packages that depend on each package, as `(name, version)`.
+    pub dependents: BTreeMap<String, BTreeSet<(String, String)>>,
+    /// Requirements known without asking a registry.
+    pub constraints: Vec<Constraint>,
+}
+
+impl DependencyGraph {
+    fn new(ecosystem: Ecosystem) -> Self {
+        Self {
+            ecosystem,
+            locked: BTreeMap::new(),
+            dependents: BTreeMap::new(),
+            constraints: Vec::new(),
+        }
+    }
+
+    /// Pick the ecosystem from the lockfiles present in `workspace`.
+    pub fn detect(workspace: &Path) -> Option<Ecosystem> {
+        if workspace.join("Cargo.lock").exists() || workspace.join("Cargo.toml").exists() {
+            Some(Ecosystem::Cargo)
+        } else if workspace.join("package-lock.json").exists() {
+            Some(Ecosystem::Npm)
+        } else if workspace.join("requirements.txt").exists() {
+            Some(Ecosystem::PyPi)
+        } else {
+            None
+        }
+    }
+
+    pub fn load(workspace: &Path, ecosystem: Ecosystem) -> Result<Self, String> {
+        match ecosystem {
+            Ecosystem::Cargo => load_cargo(workspace),
+            Ecosystem::Npm => load_npm(workspace),
+            Ecosystem::PyPi => load_pip(workspace),
+        }
+    }
+
+    /// Highest locked version of `package`.
+    pub fn current(&self, package: &str) -> Option<&Version> {
+        self.locked.get(package).and_then(|v| v.iter().max())
+    }
+
+    /// Packages the workspace depends on directly.
+    pub fn direct_dependencies(&self) -> BTreeSet<&str> {
+        self.constraints
+            .iter()
+            .filter(|c| c.direct)
+            .map(|c| c.package.as_str())
+            .collect()
+    }
+}
+
+/// Requirements placed on `package` by the workspace and by every locked
+/// package that depends on it. The second value lists dependents whose
+/// requirements could not be determined (neither cached locally nor
+/// reachable on the registry).
+pub async fn version_constraints(
+    graph: &DependencyGraph,
+    package: &str,
+    workspace: &Path,
+    registry: Option<&dyn RegistryClient>,
+) -> (Vec<Constraint>, Vec<String>) {
+    let mut constraints: Vec<Constraint> = graph
+        .constraints
+        .iter()
+        .filter(|c| c.package == package)
+        .cloned()
+        .collect();
+    let mut unknown = Vec::new();
+    if graph.ecosystem != Ecosystem::Cargo {
+        return (constraints, unknown);
+    }
+    for (name, version) in graph.dependents.get(package).into_iter().flatten() {
+        // Workspace members and path dependencies were read from their
+        // manifests already; only registry packages are left.
+        let Some(parsed) = Version::parse(version).filter(|v| {
+            graph
+                .locked
+                .get(name)
+                .is_some_and(|locked| locked.contains(v))
+        }) else {
+            continue;
+        };
+        let label = format!("{name} {version}");
+        let requirement = match locate_cached_source(workspace, name, &parsed) {
+            Some(dir) => manifest_requirement(&dir.join("Cargo.toml"), package),
+            None => match registry {
+                Some(registry) => {
+                    match registry.dependencies(Ecosystem::Cargo, name, &parsed).await {
+                        Ok(deps) => deps.into_iter().find(|(d, _)| d == package).map(|(_, r)| r),
+                        Err(_) => {
+                            unknown.push(label);
+                            continue;
+                        }
+                    }
+                }
+                None => {
+                    unknown.push(label);
+                    continue;
+                }
+            },
+        };
+        if let Some(requirement) = requirement {
+            constraints.push(Constraint {
+                package: package.to_string(),
+                requirement,
+                required_by: label,
+                direct: false,
+            });
+        }
+    }
+    (constraints, unknown)
+}
+
+/// The requirement a manifest places on `package`, in any dependency table.
+fn manifest_requirement(manifest: &Path, package: &str) -> Option<String> {
+    let text = std::fs::read_to_string(manifest).ok()?;
+    let manifest: toml::Value = toml::from_str(&text).ok()?;
+    dependency_tables(&manifest)
+        .into_iter()
+        .filter(|(kind, _)| *kind != "dev-dependencies")
+        .flat_map(|(_, table)| table.iter())
+        .find_map(|(key, spec)| {
+            let (name, requirement) = dependency_spec(key, spec)?;
+            (name == package).then_some(requirement)
+        })
+}
+
+/// `[dependencies]`-style tables of a manifest, including target-specific
+/// ones, tagged with their kind.
+fn dependency_tables(manifest: &toml::Value) -> Vec<(&str, &toml::Table)> {
+    const KINDS: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+    let mut tables: Vec<(&str, &toml::Table)> = KINDS
+        .iter()
+        .filter_map(|k| Some((*k, manifest.get(k)?.as_table()?)))
+        .collect();
+    for target in manifest
+        .get("target")
+        .and_then(|t| t.as_table())
+        .into_iter()
+        .flat_map(|t| t.values())
+    {
+        tables.extend(
+            KINDS
+                .iter()
+                .filter_map(|k| Some((*k, target.get(k)?.as_table()?))),
+        );
+    }
+    tables
+}
+
+/// `(package, requirement)` for one dependency entry. Path and git
+/// dependencies without a version have no registry requirement.
+fn dependency_spec(key: &str, spec: &toml::Value) -> Option<(String, String)> {
+    match spec {
+        toml::Value::String(req) => Some((key.to_string(), req.clone())),
+        toml::Value::Table(table) => {
+            let name = table.get("package").and_then(|p| p.as_str()).unwrap_or(key);
+            let req = table.get("version")?.as_str()?;
+            Some((name.to_string(), req.to_string()))
+        }
+        _ => None,
+    }
+}
+
+fn load_cargo(workspace: &Path) -> Result<DependencyGraph, String> {
+    let mut graph = DependencyGraph::new(Ecosystem::Cargo);
+    let lock_path = workspace.join("Cargo.lock");
+    let lock: toml::Value = std::fs::read_to_string(&lock_path)
+        .map_err(|e| format!("Cannot read {}: {e}", lock_path.display()))
+        .and_then(|text| toml::from_str(&text).map_err(|e| format!("Invalid Cargo.lock: {e}")))?;
+    let packages = lock
+        .get("package")
+        .and_then(|p| p.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut versions_of: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for package in &packages {
+        if let (Some(name), Some(version)) = (
+            package.get("name").and_then(|n| n.as_str()),
+            package.get("version").and_then(|v| v.as_str()),
+        ) {
+            versions_of
+                .entry(name.to_string())
+                .or_default()
+                .push(version.to_string());
+            // Only registry packages are candidates for updates.
+            if package.get("source").is_some()
+                && let Some(parsed) = Version::parse(version)
+            {
+                graph
+                    .locked
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(parsed);
+            }
+        }
+    }
+    for package in &packages {
+        let (Some(name), Some(version)) = (
+            package.get("name").and_then(|n| n.as_str()),
+            package.get("version").and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
+        // Entries are "name" or "name version" when the name is ambiguous.
+        for dep in package
+            .get("dependencies")
+            .and_then(|d| d.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|d| d.as_str())
+        {
+            let dep_name = dep.split_whitespace().next().unwrap_or(dep);
+            graph
+                .dependents
+                .entry(dep_name.to_string())
+                .or_default()
+                .insert((name.to_string(), version.to_string()));
+        }
+    }
+
+    // Workspace manifests, with `workspace = true` resolved against the root.
+    let root: Option<toml::Value> = std::fs::read_to_string(workspace.join("Cargo.toml"))
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok());
+    let workspace_deps = root
+        .as_ref()
+        .and_then(|r| r.get("workspace"))
+        .and_then(|w| w.get("dependencies"))
+        .and_then(|d| d.as_table());
+    for manifest_path in workspace_manifests(workspace) {
+        let Some(manifest) = std::fs::read_to_string(&manifest_path)
+            .ok()
+            .and_then(|text| toml::from_str::<toml::Value>(&text).ok())
+        else {
+            continue;
+        };
+        let member = manifest
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .unwrap_or("workspace");
+        let relative = manifest_path
+            .strip_prefix(workspace)
+            .unwrap_or(&manifest_path)
+            .display()
+            .to_string();
+        for (_, table) in dependency_tables(&manifest) {
+            for (key, spec) in table {
+                let inherited = spec
+                    .get("workspace")
+                    .and_then(|w| w.as_bool())
+                    .unwrap_or(false);
+                let resolved = if inherited {
+                    workspace_deps.and_then(|deps| deps.get(key))
+                } else {
+                    Some(spec)
+                };
+                if let Some((package, requirement)) =
+                    resolved.and_then(|spec| dependency_spec(key, spec))
+                {
+                    graph.constraints.push(Constraint {
+                        package,
+                        requirement,
+                        required_by: format!("{member} ({relative})"),
+                        direct: true,
+                    });
+                }
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/// Cargo manifests belonging to the workspace, skipping build output and
+/// vendored sources.
+fn workspace_manifests(workspace: &Path) -> Vec<PathBuf> {
+    WalkDir::new(workspace)
+        .max_depth(4)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0
+                || !(name.starts_with('.')
+                    || matches!(&*name, "target" | "vendor" | "node_modules"))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name() == "Cargo.toml")
+        .map(|e| e.into_path())
+        .collect()
+}
+
+fn load_npm(workspace: &Path) -> Result<DependencyGraph, String> {
+    let mut graph = DependencyGraph::new(Ecosystem::Npm);
+    let lock_path = workspace.join("package-lock.json");
+    let lock: serde_json::Value = std::fs::read_to_string(&lock_path)
+        .map_err(|e| format!("Cannot read {}: {e}", lock_path.display()))
+        .and_then(|text| {
+            serde_json::from_str(&text).map_err(|e| format!("Invalid package-lock.json: {e}"))
+        })?;
+    let Some(packages) = lock["packages"].as_object() else {
+        return Err("package-lock.json has no `packages` map (lockfile v1 is not supported; run `npm install` with npm 7+)".to_string());
+    };
+    for (path, entry) in packages {
+        let name = entry["name"].as_str().map(String::from).or_else(|| {
+            path.rsplit_once("node_modules/")
+                .map(|(_, n)| n.to_string())
+        });
+        let version = entry["version"].as_str().unwrap_or_default();
+        let is_root = path.is_empty();
+        if !is_root
+            && let Some(name) = &name
+            && entry["link"] != true
+            && let Some(parsed) = Version::parse(version)
+        {
+            graph.locked.entry(name.clone()).or_default().push(parsed);
+        }
+        let required_by = if is_root {
+            "package.json".to_string()
+        } else {
+            format!("{} {version}", name.as_deref().unwrap_or(path))
+        };
+        for kind in [
+            "dependencies",
+            "devDependencies",
+            "peerDependencies",
+            "optionalDependencies",
+        ] {
+            if kind == "devDependencies" && !is_root {
+                continue;
+            }
+            for (dep, requirement) in entry[kind].as_object().into_iter().flatten() {
+                let Some(requirement) = requirement.as_str() else {
+                    continue;
+                };
+                if !is_root {
+                    graph
+                        .dependents
+                        .entry(dep.clone())
+                        .or_default()
+                        .insert((name.clone().unwrap_or_default(), version.to_string()));
+                }
+                graph.constraints.push(Constraint {
+                    package: dep.clone(),
+                    requirement: requirement.to_string(),
+                    required_by: required_by.clone(),
+                    direct: is_root,
+                });
+            }
+        }
+    }
+    Ok(graph)
+}
+
+fn load_pip(workspace: &Path) -> Result<DependencyGraph, String> {
+    let mut graph = DependencyGraph::new(Ecosystem::PyPi);
+    let path = workspace.join("requirements.txt");
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let line = line.split(';').next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('-') || line.contains("://") {
+            continue;
+        }
+        let split = line
+            .find(|c: char| "<>=!~[ ".contains(c))
+            .unwrap_or(line.len());
+        let name = line[..split].trim().to_ascii_lowercase().replace('_', "-");
+        let rest = line[split..].trim();
+        let requirement = match rest.strip_prefix('[') {
+            Some(extras) => extras.split_once(']').map(|(_, r)| r).unwrap_or_default(),
+            None => rest,
+        }
+        .trim();
+        if let Some(pinned) = requirement.strip_prefix("==")
+            && !pinned.contains(['*', ','])
+            && let Some(version) = Version::parse(pinned)
+        {
+            graph.locked.entry(name.clone()).or_default().push(version);
+        }
+        graph.constraints.push(Constraint {
+            package: name,
+            requirement: requirement.to_string(),
+            required_by: "requirements.txt".to_string(),
+            direct: true,
+        });
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_npm_lockfile() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("package-lock.json"),
+            r#"{"lockfileVersion": 3, "packages": {
+                "": {"name": "app", "dependencies": {"lodash": "^4.17.0", "left": "^1.0.0"}},
+                "node_modules/lodash": {"version": "4.17.21"},
+                "node_modules/left": {"version": "1.2.0", "dependencies": {"lodash": "~4.17.20"}}
+            }}"#,
+        )
+        .unwrap();
+        let graph = DependencyGraph::load(dir.path(), Ecosystem::Npm).unwrap();
+        assert_eq!(graph.current("lodash"), Version::parse("4.17.21").as_ref());
+        assert_eq!(
+            graph.direct_dependencies(),
+            BTreeSet::from(["left", "lodash"])
+        );
+        let on_lodash: Vec<_> = graph
+            .constraints
+            .iter()
+            .filter(|c| c.package == "lodash")
+            .map(|c| (c.requirement.as_str(), c.required_by.as_str()))
+            .collect();
+        assert_eq!(
+            on_lodash,
+            vec![("^4.17.0", "package.json"), ("~4.17.20", "left 1.2.0")]
+        );
+    }
+}
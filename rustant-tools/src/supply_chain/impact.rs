@@ -0,0 +1,658 @@
+//! Update impact analysis: what happens if a dependency is bumped.
+//!
+//! For one package and target version this combines the dependency graph
+//! (requirements the bump would violate), advisories fixed or still open,
+//! release notes between the two versions, and for Rust crates a comparison
+//! of the public API. Each source that cannot be reached is recorded in
+//! [`ImpactReport::degraded`] so the report says which conclusions rest on
+//! local data only.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use super::advisory::{Advisory, load_rustsec, local_advisory_db};
+use super::api_surface::{ApiDiff, SOURCE_CACHE_DIR, locate_cached_source, scan_crate};
+use super::graph::{DependencyGraph, version_constraints};
+use super::registry::{PackageInfo, RegistryClient, RegistryError, ReleaseNote};
+use super::version::{Ecosystem, Version};
+
+/// Release-note lines that usually announce a breaking change.
+const BREAKING_MARKERS: [&str; 7] = [
+    "breaking",
+    "removed",
+    "renamed",
+    "no longer",
+    "incompatible",
+    "msrv",
+    "minimum supported rust version",
+];
+
+/// Output lines of `cargo check` kept in a `--try` result.
+const TRY_OUTPUT_LINES: usize = 40;
+
+/// Upper bound on each step of a `--try` run.
+const TRY_STEP_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Recommendation {
+    Safe,
+    ReviewChangelog,
+    Breaking,
+}
+
+impl fmt::Display for Recommendation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Safe => "safe",
+            Self::ReviewChangelog => "review changelog",
+            Self::Breaking => "breaking",
+        })
+    }
+}
+
+/// A requirement that admits the current version but not the target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub required_by: String,
+    pub requirement: String,
+    /// Declared in the workspace's own manifest, so fixable by editing it.
+    pub direct: bool,
+}
+
+/// Outcome of applying the bump in a scratch checkout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TryResult {
+    pub success: bool,
+    /// The last command run.
+    pub command: String,
+    /// Tail of its output.
+    pub output: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImpactReport {
+    pub package: String,
+    pub ecosystem: Ecosystem,
+    pub current: Version,
+    pub target: Version,
+    pub conflicts: Vec<Conflict>,
+    /// Dependents whose requirements could not be read.
+    pub unresolved: Vec<String>,
+    pub advisories_fixed: Vec<Advisory>,
+    pub advisories_remaining: Vec<Advisory>,
+    pub notes: Vec<ReleaseNote>,
+    pub breaking_hints: Vec<String>,
+    pub api: Option<ApiDiff>,
+    /// The registry was unreachable, leaving the dependency graph and
+    /// advisories as the only inputs.
+    pub offline: bool,
+    /// Sources that were unavailable; non-empty means a partial analysis.
+    pub degraded: Vec<String>,
+    pub try_result: Option<TryResult>,
+    pub recommendation: Recommendation,
+    pub reasons: Vec<String>,
+}
+
+impl ImpactReport {
+    /// Derive the recommendation from everything collected so far.
+    pub fn recommend(&mut self) {
+        let mut reasons = Vec::new();
+        let mut level = Recommendation::Safe;
+        let mut raise = |to: Recommendation, reason: String| {
+            level = level.max(to);
+            reasons.push(reason);
+        };
+
+        if let Some(result) = &self.try_result
+            && !result.success
+        {
+            raise(
+                Recommendation::Breaking,
+                format!("`{}` failed in a scratch checkout", result.command),
+            );
+        }
+        for conflict in self.conflicts.iter().filter(|c| !c.direct) {
+            raise(
+                Recommendation::Breaking,
+                format!(
+                    "{} requires {} {}, which excludes {}",
+                    conflict.required_by, self.package, conflict.requirement, self.target
+                ),
+            );
+        }
+        let compatible = self
+            .current
+            .is_compatible_with(&self.target, self.ecosystem);
+        match &self.api {
+            Some(api) if api.is_breaking() => raise(
+                Recommendation::Breaking,
+                format!(
+                    "public API: {} item(s) removed, {} changed",
+                    api.removed.len(),
+                    api.changed.len()
+                ),
+            ),
+            Some(_) if !compatible => raise(
+                Recommendation::ReviewChangelog,
+                "semver-incompatible bump, though no public API removals were found".to_string(),
+            ),
+            None if !compatible => raise(
+                Recommendation::Breaking,
+                format!(
+                    "semver-incompatible {} bump",
+                    self.current.bump_kind(&self.target)
+                ),
+            ),
+            _ => {}
+        }
+        if !self.breaking_hints.is_empty() {
+            raise(
+                Recommendation::ReviewChangelog,
+                format!(
+                    "release notes mention {} possibly breaking change(s)",
+                    self.breaking_hints.len()
+                ),
+            );
+        }
+        if !self.degraded.is_empty() {
+            raise(
+                Recommendation::ReviewChangelog,
+                "analysis incomplete; see the unavailable sources above".to_string(),
+            );
+        }
+        if !self.unresolved.is_empty() {
+            raise(
+                Recommendation::ReviewChangelog,
+                format!(
+                    "requirements of {} dependent(s) could not be checked",
+                    self.unresolved.len()
+                ),
+            );
+        }
+        if reasons.is_empty() {
+            reasons.push(format!(
+                "{} bump within compatible range, no conflicts or breaking notes",
+                self.current.bump_kind(&self.target)
+            ));
+        }
+        self.recommendation = level;
+        self.reasons = reasons;
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "{} {} → {} ({}, {} bump): {}\n",
+            self.package,
+            self.current,
+            self.target,
+            self.ecosystem.registry(),
+            self.current.bump_kind(&self.target),
+            self.recommendation.to_string().to_uppercase()
+        );
+        if self.offline {
+            out.push_str("  [OFFLINE: graph-and-advisory-only analysis, registry unreachable]\n");
+        }
+        for source in &self.degraded {
+            out.push_str(&format!("  unavailable: {source}\n"));
+        }
+        for reason in &self.reasons {
+            out.push_str(&format!("  - {reason}\n"));
+        }
+
+        if self.conflicts.is_empty() {
+            out.push_str("  Constraints: no conflicts\n");
+        } else {
+            out.push_str("  Constraints:\n");
+            for conflict in &self.conflicts {
+                let fix = if conflict.direct {
+                    " (edit the manifest)"
+                } else {
+                    ""
+                };
+                out.push_str(&format!(
+                    "    {} requires {}{}\n",
+                    conflict.required_by, conflict.requirement, fix
+                ));
+            }
+        }
+        if !self.unresolved.is_empty() {
+            out.push_str(&format!(
+                "    unchecked dependents: {}\n",
+                self.unresolved.join(", ")
+            ));
+        }
+
+        for (label, advisories) in [
+            ("Advisories fixed", &self.advisories_fixed),
+            ("Advisories still open", &self.advisories_remaining),
+        ] {
+            if !advisories.is_empty() {
+                out.push_str(&format!("  {label}:\n"));
+                for advisory in advisories {
+                    out.push_str(&format!("    {} {}\n", advisory.id, advisory.summary));
+                }
+            }
+        }
+
+        if let Some(api) = &self.api {
+            out.push_str(&format!(
+                "  Public API: {} removed, {} changed, {} added\n",
+                api.removed.len(),
+                api.changed.len(),
+                api.added.len()
+            ));
+            for path in api.removed.iter().take(10) {
+                out.push_str(&format!("    - {path}\n"));
+            }
+            for (path, _, new) in api.changed.iter().take(10) {
+                out.push_str(&format!("    ~ {path}: {new}\n"));
+            }
+        }
+
+        if !self.notes.is_empty() {
+            let source = &self.notes[0].source;
+            out.push_str(&format!(
+                "  Release notes ({} release(s), from {source}):\n",
+                self.notes.len()
+            ));
+            for note in &self.notes {
+                out.push_str(&format!("    {} — {}\n", note.version, note.title));
+            }
+        }
+        for hint in &self.breaking_hints {
+            out.push_str(&format!("    ! {hint}\n"));
+        }
+
+        if let Some(result) = &self.try_result {
+            out.push_str(&format!(
+                "  Try: `{}` {}\n",
+                result.command,
+                if result.success {
+                    "succeeded"
+                } else {
+                    "failed"
+                }
+            ));
+            for line in result.output.lines() {
+                out.push_str(&format!("    | {line}\n"));
+            }
+        }
+        out
+    }
+}
+
+/// One-line-per-package summary of several reports.
+pub fn summarize(reports: &[ImpactReport]) -> String {
+    let count = |r: Recommendation| {
+        reports
+            .iter()
+            .filter(|report| report.recommendation == r)
+            .count()
+    };
+    let fixed: usize = reports.iter().map(|r| r.advisories_fixed.len()).sum();
+    let mut out = format!(
+        "Summary: {} update(s) — {} safe, {} to review, {} breaking; {} advisory fix(es)",
+        reports.len(),
+        count(Recommendation::Safe),
+        count(Recommendation::ReviewChangelog),
+        count(Recommendation::Breaking),
+        fixed
+    );
+    let offline = reports.iter().filter(|r| r.offline).count();
+    if offline > 0 {
+        out.push_str(&format!(
+            "; {offline} analyzed offline (graph and advisories only)"
+        ));
+    }
+    out
+}
+
+pub struct ImpactAnalyzer<'a> {
+    pub workspace: &'a Path,
+    pub graph: &'a DependencyGraph,
+    pub registry: &'a dyn RegistryClient,
+}
+
+impl ImpactAnalyzer<'_> {
+    /// Analyze updating `package` to `target`, or to its latest release.
+    pub async fn analyze(
+        &self,
+        package: &str,
+        target: Option<Version>,
+    ) -> Result<ImpactReport, String> {
+        let info = self.registry.package(self.graph.ecosystem, package).await;
+        self.analyze_with(package, target, info).await
+    }
+
+    /// Like [`analyze`](Self::analyze) with registry metadata already fetched.
+    pub async fn analyze_with(
+        &self,
+        package: &str,
+        target: Option<Version>,
+        info: Result<PackageInfo, RegistryError>,
+    ) -> Result<ImpactReport, String> {
+        let ecosystem = self.graph.ecosystem;
+        let current = self.graph.current(package).cloned().ok_or_else(|| {
+            format!(
+                "'{package}' is not a locked {} dependency",
+                ecosystem.registry()
+            )
+        })?;
+
+        let mut degraded = Vec::new();
+        let info = match info {
+            Ok(info) => Some(info),
+            Err(RegistryError::NotFound(_)) => {
+                return Err(format!(
+                    "'{package}' was not found on {}",
+                    ecosystem.registry()
+                ));
+            }
+            Err(e) => {
+                degraded.push(format!("registry metadata and release notes ({e})"));
+                None
+            }
+        };
+        let target = match (target, &info) {
+            (Some(target), Some(info)) if !info.has(&target) => {
+                return Err(format!(
+                    "{package} {target} is not published on {}",
+                    ecosystem.registry()
+                ));
+            }
+            (Some(target), _) => target,
+            (None, Some(info)) => info
+                .latest()
+                .cloned()
+                .ok_or_else(|| format!("{package} has no stable release"))?,
+            (None, None) => {
+                return Err(format!(
+                    "{} is unavailable, so the latest {package} release is unknown; pass `version` for a graph-and-advisory-only analysis",
+                    ecosystem.registry()
+                ));
+            }
+        };
+
+        // Requirements the bump would violate.
+        let registry = info.as_ref().map(|_| self.registry);
+        let (constraints, unresolved) =
+            version_constraints(self.graph, package, self.workspace, registry).await;
+        let conflicts = constraints
+            .iter()
+            .filter_map(|c| {
+                let requirement = c.parsed(ecosystem)?;
+                (requirement.matches(&current) && !requirement.matches(&target)).then(|| Conflict {
+                    required_by: c.required_by.clone(),
+                    requirement: c.requirement.clone(),
+                    direct: c.direct,
+                })
+            })
+            .collect();
+
+        // Advisories, from OSV or the local RustSec database.
+        let advisories = match self.registry.advisories(ecosystem, package).await {
+            Ok(advisories) => advisories,
+            Err(e) => match (ecosystem, local_advisory_db()) {
+                (Ecosystem::Cargo, Some(db)) => {
+                    degraded.push(format!("OSV ({e}); used the local RustSec database"));
+                    load_rustsec(&db, package)
+                }
+                _ => {
+                    degraded.push(format!("advisories ({e})"));
+                    Vec::new()
+                }
+            },
+        };
+        let (advisories_remaining, advisories_fixed): (Vec<_>, Vec<_>) = advisories
+            .into_iter()
+            .filter(|a| a.affects(&current) || a.affects(&target))
+            .partition(|a| a.affects(&target));
+
+        // Public API and changelog of Rust crates.
+        let mut notes = Vec::new();
+        let mut api = None;
+        if ecosystem == Ecosystem::Cargo {
+            let old = self.crate_source(package, &current, info.is_some()).await;
+            let new = self.crate_source(package, &target, info.is_some()).await;
+            match (&old, &new) {
+                (Some(old), Some(new)) => {
+                    api = Some(ApiDiff::between(&scan_crate(old), &scan_crate(new)));
+                }
+                _ => degraded.push("crate sources for the public API diff".to_string()),
+            }
+            if let Some(new) = &new {
+                notes = changelog_notes(new, &current, &target);
+            }
+        }
+        if let Some(repository) = info.as_ref().and_then(|i| i.repository.as_deref()) {
+            match self.registry.releases(repository).await {
+                Ok(releases) => {
+                    let mut releases: Vec<ReleaseNote> = releases
+                        .into_iter()
+                        .filter(|n| n.version > current && n.version <= target)
+                        .collect();
+                    if !releases.is_empty() {
+                        releases.sort_by(|a, b| a.version.cmp(&b.version));
+                        releases.dedup_by(|a, b| a.version == b.version);
+                        notes = releases;
+                    }
+                }
+                Err(e) if notes.is_empty() => degraded.push(format!("GitHub releases ({e})")),
+                Err(_) => {}
+            }
+        }
+        let breaking_hints = breaking_hints(&notes);
+
+        let mut report = ImpactReport {
+            package: package.to_string(),
+            ecosystem,
+            current,
+            target,
+            conflicts,
+            unresolved,
+            advisories_fixed,
+            advisories_remaining,
+            notes,
+            breaking_hints,
+            api,
+            offline: info.is_none(),
+            degraded,
+            try_result: None,
+            recommendation: Recommendation::Safe,
+            reasons: Vec::new(),
+        };
+        report.recommend();
+        Ok(report)
+    }
+
+    /// Unpacked source of a crate version, downloading it when allowed.
+    async fn crate_source(&self, name: &str, version: &Version, online: bool) -> Option<PathBuf> {
+        if let Some(dir) = locate_cached_source(self.workspace, name, version) {
+            return Some(dir);
+        }
+        if !online {
+            return None;
+        }
+        let dest = self.workspace.join(SOURCE_CACHE_DIR);
+        self.registry
+            .download_crate(name, version, &dest)
+            .await
+            .ok()
+            .filter(|dir| dir.join("Cargo.toml").is_file())
+    }
+}
+
+/// Lines of release notes that look like breaking-change announcements.
+fn breaking_hints(notes: &[ReleaseNote]) -> Vec<String> {
+    notes
+        .iter()
+        .flat_map(|note| {
+            note.body.lines().filter_map(move |line| {
+                let trimmed = line.trim().trim_start_matches(['-', '*', '#', ' ']);
+                let lower = trimmed.to_lowercase();
+                BREAKING_MARKERS
+                    .iter()
+                    .any(|m| lower.contains(m))
+                    .then(|| format!("{}: {}", note.version, truncate(trimmed, 160)))
+            })
+        })
+        .take(10)
+        .collect()
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+/// Sections of the crate's `CHANGELOG.md` for versions in `(current, target]`.
+fn changelog_notes(root: &Path, current: &Version, target: &Version) -> Vec<ReleaseNote> {
+    let Some(text) = ["CHANGELOG.md", "CHANGES.md", "RELEASES.md"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(root.join(name)).ok())
+    else {
+        return Vec::new();
+    };
+    let mut notes: Vec<ReleaseNote> = Vec::new();
+    let mut collecting = false;
+    for line in text.lines() {
+        if line.starts_with('#') {
+            let version = line
+                .split(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '-'))
+                .filter(|word| word.contains('.'))
+                .find_map(Version::parse);
+            if let Some(version) = version {
+                collecting = version > *current && version <= *target;
+                if collecting {
+                    notes.push(ReleaseNote {
+                        version,
+                        title: line.trim_start_matches('#').trim().to_string(),
+                        body: String::new(),
+                        source: "CHANGELOG.md".to_string(),
+                    });
+                }
+                continue;
+            }
+        }
+        if collecting && let Some(note) = notes.last_mut() {
+            note.body.push_str(line);
+            note.body.push('\n');
+        }
+    }
+    notes.sort_by(|a, b| a.version.cmp(&b.version));
+    notes
+}
+
+/// Apply the bump in a scratch git worktree and run `cargo check` there.
+/// Build artifacts go to a shared directory under `target/` so repeated
+/// runs reuse the dependency build.
+pub async fn try_update(
+    workspace: &Path,
+    package: &str,
+    current: &Version,
+    target: &Version,
+) -> TryResult {
+    let scratch = std::env::temp_dir().join(format!(
+        "rustant-try-{package}-{}",
+        uuid::Uuid::new_v4().simple()
+    ));
+    let scratch_arg = scratch.to_string_lossy().to_string();
+    let (ok, output) = run(
+        workspace,
+        "git",
+        &["worktree", "add", "--detach", &scratch_arg, "HEAD"],
+        None,
+    )
+    .await;
+    if !ok {
+        return TryResult {
+            success: false,
+            command: "git worktree add".to_string(),
+            output: format!("Could not create a scratch checkout: {output}"),
+        };
+    }
+
+    // The worktree has HEAD's files; carry over an uncommitted lockfile and
+    // find the project inside it if the workspace is a subdirectory.
+    let (_, prefix) = run(workspace, "git", &["rev-parse", "--show-prefix"], None).await;
+    let project = scratch.join(prefix.trim());
+    let _ = std::fs::copy(workspace.join("Cargo.lock"), project.join("Cargo.lock"));
+    let target_dir = workspace.join("target").join("supply-chain-try");
+
+    let spec = format!("{package}@{current}");
+    let precise = target.to_string();
+    let steps: [Vec<&str>; 2] = [
+        vec!["update", "-p", &spec, "--precise", &precise],
+        vec![
+            "check",
+            "--workspace",
+            "--all-targets",
+            "--message-format",
+            "short",
+        ],
+    ];
+    let mut result = TryResult {
+        success: true,
+        command: String::new(),
+        output: String::new(),
+    };
+    for args in &steps {
+        let (ok, output) = run(&project, "cargo", args, Some(&target_dir)).await;
+        result = TryResult {
+            success: ok,
+            command: format!("cargo {}", args.join(" ")),
+            output: tail(&output, TRY_OUTPUT_LINES),
+        };
+        if !ok {
+            break;
+        }
+    }
+
+    run(
+        workspace,
+        "git",
+        &["worktree", "remove", "--force", &scratch_arg],
+        None,
+    )
+    .await;
+    result
+}
+
+/// Run a command, returning success and its combined output.
+async fn run(
+    dir: &Path,
+    program: &str,
+    args: &[&str],
+    target_dir: Option<&Path>,
+) -> (bool, String) {
+    let mut command = tokio::process::Command::new(program);
+    command
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    if let Some(target_dir) = target_dir {
+        command.env("CARGO_TARGET_DIR", target_dir);
+    }
+    match tokio::time::timeout(TRY_STEP_TIMEOUT, command.output()).await {
+        Ok(Ok(output)) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            (output.status.success(), text)
+        }
+        Ok(Err(e)) => (false, format!("failed to run {program}: {e}")),
+        Err(_) => (
+            false,
+            format!("{program} timed out after {}s", TRY_STEP_TIMEOUT.as_secs()),
+        ),
+    }
+}
+
+fn tail(text: &str, lines: usize) -> String {
+    let all: Vec<&str> = text.lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
@@ -0,0 +1,504 @@
+//! Supply chain check tool — what happens if a dependency is updated.
+//!
+//! `update_impact` reports, per package, the version constraints a bump
+//! would violate, advisories it fixes, release notes and breaking-change
+//! hints, and for Rust crates a public API comparison and an optional
+//! `cargo check` in a scratch checkout. When a registry is unreachable the
+//! report falls back to the dependency graph and advisories and says so.
+
+pub mod advisory;
+pub mod api_surface;
+pub mod graph;
+pub mod impact;
+pub mod registry;
+pub mod version;
+
+use async_trait::async_trait;
+use rustant_core::error::ToolError;
+use rustant_core::types::{RiskLevel, ToolOutput};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::registry::Tool;
+use graph::{DependencyGraph, version_constraints};
+use impact::{ImpactAnalyzer, ImpactReport, summarize, try_update};
+use registry::{HttpRegistry, RegistryClient, RegistryError};
+use version::{Ecosystem, Version};
+
+/// Packages analyzed by `all_outdated` unless `limit` says otherwise.
+const DEFAULT_OUTDATED_LIMIT: usize = 20;
+
+pub struct SupplyChainCheckTool {
+    workspace: PathBuf,
+    registry: Arc<dyn RegistryClient>,
+}
+
+impl SupplyChainCheckTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self::with_registry(workspace, Arc::new(HttpRegistry::new()))
+    }
+
+    pub fn with_registry(workspace: PathBuf, registry: Arc<dyn RegistryClient>) -> Self {
+        Self {
+            workspace,
+            registry,
+        }
+    }
+
+    fn load_graph(&self, args: &Value) -> Result<DependencyGraph, String> {
+        let ecosystem = match opt_arg(args, "ecosystem") {
+            Some(name) => Ecosystem::parse(&name)
+                .ok_or_else(|| format!("Unknown ecosystem '{name}'. Use cargo, npm or pypi."))?,
+            None => DependencyGraph::detect(&self.workspace).ok_or_else(|| {
+                "No Cargo.lock, package-lock.json or requirements.txt in the workspace.".to_string()
+            })?,
+        };
+        DependencyGraph::load(&self.workspace, ecosystem)
+    }
+
+    async fn update_impact(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let graph = match self.load_graph(args) {
+            Ok(graph) => graph,
+            Err(message) => return Ok(ToolOutput::text(message)),
+        };
+        let analyzer = ImpactAnalyzer {
+            workspace: &self.workspace,
+            graph: &graph,
+            registry: self.registry.as_ref(),
+        };
+        let try_build = args.get("try").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let mut reports = Vec::new();
+        let mut errors = Vec::new();
+        if args
+            .get("all_outdated")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            let limit = args
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .map(|n| n as usize)
+                .unwrap_or(DEFAULT_OUTDATED_LIMIT);
+            for package in graph.direct_dependencies() {
+                if reports.len() >= limit {
+                    break;
+                }
+                let Some(current) = graph.current(package) else {
+                    continue;
+                };
+                let info = match self.registry.package(graph.ecosystem, package).await {
+                    Ok(info) => info,
+                    Err(e @ RegistryError::Unavailable(..)) => {
+                        return Ok(ToolOutput::text(format!(
+                            "Cannot list outdated packages: {e}. Pass `package` and `version` \
+                             for a graph-and-advisory-only analysis of a specific update."
+                        )));
+                    }
+                    Err(e) => {
+                        errors.push(format!("{package}: {e}"));
+                        continue;
+                    }
+                };
+                let Some(latest) = info.latest().filter(|latest| *latest > current).cloned() else {
+                    continue;
+                };
+                match analyzer.analyze_with(package, Some(latest), Ok(info)).await {
+                    Ok(report) => reports.push(report),
+                    Err(e) => errors.push(format!("{package}: {e}")),
+                }
+            }
+            if reports.is_empty() && errors.is_empty() {
+                return Ok(ToolOutput::text(format!(
+                    "All direct {} dependencies are up to date.",
+                    graph.ecosystem.registry()
+                )));
+            }
+        } else {
+            let package = str_arg(args, "package");
+            if package.is_empty() {
+                return Err(ToolError::InvalidArguments {
+                    name: "supply_chain_check".to_string(),
+                    reason: "'package' is required unless 'all_outdated' is set".to_string(),
+                });
+            }
+            let target = match opt_arg(args, "version") {
+                Some(text) => {
+                    Some(
+                        Version::parse(&text).ok_or_else(|| ToolError::InvalidArguments {
+                            name: "supply_chain_check".to_string(),
+                            reason: format!("'{text}' is not a version"),
+                        })?,
+                    )
+                }
+                None => None,
+            };
+            match analyzer.analyze(package, target).await {
+                Ok(report) => reports.push(report),
+                Err(message) => return Ok(ToolOutput::text(message)),
+            }
+        }
+
+        if try_build {
+            if graph.ecosystem == Ecosystem::Cargo {
+                for report in &mut reports {
+                    report.try_result = Some(
+                        try_update(
+                            &self.workspace,
+                            &report.package,
+                            &report.current,
+                            &report.target,
+                        )
+                        .await,
+                    );
+                    report.recommend();
+                }
+            } else {
+                errors.push("`try`: it only applies to Rust packages".to_string());
+            }
+        }
+        Ok(ToolOutput::text(render_reports(&reports, &errors)))
+    }
+
+    async fn constraints(&self, args: &Value) -> Result<ToolOutput, ToolError> {
+        let package = str_arg(args, "package");
+        if package.is_empty() {
+            return Err(ToolError::InvalidArguments {
+                name: "supply_chain_check".to_string(),
+                reason: "'package' is required".to_string(),
+            });
+        }
+        let graph = match self.load_graph(args) {
+            Ok(graph) => graph,
+            Err(message) => return Ok(ToolOutput::text(message)),
+        };
+        let (constraints, unresolved) = version_constraints(
+            &graph,
+            package,
+            &self.workspace,
+            Some(self.registry.as_ref()),
+        )
+        .await;
+        let target = opt_arg(args, "version").and_then(|v| Version::parse(&v));
+
+        let mut out = match graph.current(package) {
+            Some(current) => format!("{package} (locked at {current})\n"),
+            None => format!("{package} (not locked)\n"),
+        };
+        if constraints.is_empty() {
+            out.push_str("  No requirements found.\n");
+        }
+        for constraint in &constraints {
+            let verdict = match (&target, constraint.parsed(graph.ecosystem)) {
+                (Some(target), Some(req)) if req.matches(target) => format!(" — admits {target}"),
+                (Some(target), Some(_)) => format!(" — EXCLUDES {target}"),
+                _ => String::new(),
+            };
+            out.push_str(&format!(
+                "  {} ← {}{}\n",
+                constraint.requirement, constraint.required_by, verdict
+            ));
+        }
+        if !unresolved.is_empty() {
+            out.push_str(&format!(
+                "  Unchecked (registry unavailable): {}\n",
+                unresolved.join(", ")
+            ));
+        }
+        Ok(ToolOutput::text(out.trim_end()))
+    }
+}
+
+fn render_reports(reports: &[ImpactReport], errors: &[String]) -> String {
+    let mut out = reports
+        .iter()
+        .map(ImpactReport::render)
+        .collect::<Vec<_>>()
+        .join("\n");
+    for error in errors {
+        out.push_str(&format!("Skipped {error}\n"));
+    }
+    if !reports.is_empty() {
+        out.push('\n');
+        out.push_str(&summarize(reports));
+    }
+    out.trim_end().to_string()
+}
+
+#[async_trait]
+impl Tool for SupplyChainCheckTool {
+    fn name(&self) -> &str {
+        "supply_chain_check"
+    }
+    fn description(&self) -> &str {
+        "Check what a dependency update would do before taking it. Actions: update_impact \
+         (for a package and target version, or all_outdated: constraint conflicts, advisories \
+         fixed, release notes, breaking-change hints, public API diff for Rust crates, and a \
+         safe / review changelog / breaking recommendation; try=true runs cargo check on the \
+         bump in a scratch checkout), constraints (every requirement placed on a package)."
+    }
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["update_impact", "constraints"],
+                    "description": "Action to perform"
+                },
+                "package": { "type": "string", "description": "Package name" },
+                "version": { "type": "string", "description": "Target version (update_impact defaults to the latest release)" },
+                "all_outdated": { "type": "boolean", "description": "Analyze every outdated direct dependency (update_impact)", "default": false },
+                "limit": { "type": "integer", "description": "Maximum packages for all_outdated (default: 20)" },
+                "ecosystem": {
+                    "type": "string",
+                    "enum": ["cargo", "npm", "pypi"],
+                    "description": "Ecosystem (default: detected from the lockfile)"
+                },
+                "try": { "type": "boolean", "description": "Rust only: apply the bump in a scratch git worktree and run cargo check", "default": false }
+            },
+            "required": ["action"]
+        })
+    }
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Network
+    }
+    fn timeout(&self) -> Duration {
+        // `try` builds the workspace.
+        Duration::from_secs(900)
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolOutput, ToolError> {
+        match str_arg(&args, "action") {
+            "update_impact" => self.update_impact(&args).await,
+            "constraints" => self.constraints(&args).await,
+            other => Ok(ToolOutput::text(format!(
+                "Unknown action: '{other}'. Use: update_impact, constraints"
+            ))),
+        }
+    }
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> &'a str {
+    args.get(key).and_then(|v| v.as_str()).unwrap_or("").trim()
+}
+
+fn opt_arg(args: &Value, key: &str) -> Option<String> {
+    Some(str_arg(args, key))
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::advisory::{Advisory, Affected};
+    use super::registry::{PackageInfo, Release, ReleaseNote};
+    use super::*;
+    use std::path::Path;
+
+    /// Registry with fixed answers; `online: false` fails every request the
+    /// way an unreachable network does.
+    struct FakeRegistry {
+        online: bool,
+        versions: Vec<&'static str>,
+        notes: Vec<ReleaseNote>,
+        advisories: Vec<Advisory>,
+    }
+
+    impl FakeRegistry {
+        fn check(&self) -> Result<(), RegistryError> {
+            if self.online {
+                Ok(())
+            } else {
+                Err(RegistryError::Unavailable(
+                    "crates.io".to_string(),
+                    "connection refused".to_string(),
+                ))
+            }
+        }
+    }
+
+    #[async_trait]
+    impl RegistryClient for FakeRegistry {
+        async fn package(&self, _: Ecosystem, _: &str) -> Result<PackageInfo, RegistryError> {
+            self.check()?;
+            Ok(PackageInfo {
+                releases: self
+                    .versions
+                    .iter()
+                    .map(|v| Release {
+                        version: Version::parse(v).unwrap(),
+                        yanked: false,
+                    })
+                    .collect(),
+                repository: Some("https://github.com/example/demo".to_string()),
+            })
+        }
+        async fn dependencies(
+            &self,
+            _: Ecosystem,
+            _: &str,
+            _: &Version,
+        ) -> Result<Vec<(String, String)>, RegistryError> {
+            self.check()?;
+            Ok(Vec::new())
+        }
+        async fn releases(&self, _: &str) -> Result<Vec<ReleaseNote>, RegistryError> {
+            self.check()?;
+            Ok(self.notes.clone())
+        }
+        async fn advisories(&self, _: Ecosystem, _: &str) -> Result<Vec<Advisory>, RegistryError> {
+            self.check()?;
+            Ok(self.advisories.clone())
+        }
+        async fn download_crate(
+            &self,
+            name: &str,
+            _: &Version,
+            _: &Path,
+        ) -> Result<PathBuf, RegistryError> {
+            self.check()?;
+            Err(RegistryError::NotFound(name.to_string()))
+        }
+    }
+
+    /// A workspace locking `demo 1.2.0`, which the registry crate
+    /// `consumer 0.5.0` (vendored) requires as `~1.2`.
+    fn workspace() -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        let registry = "registry+https://github.com/rust-lang/crates.io-index";
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\ndemo = \"1.2\"\nconsumer = \"0.5\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("Cargo.lock"),
+            format!(
+                "version = 3\n\n[[package]]\nname = \"app\"\nversion = \"0.1.0\"\ndependencies = [\"consumer\", \"demo\"]\n\n\
+                 [[package]]\nname = \"consumer\"\nversion = \"0.5.0\"\nsource = \"{registry}\"\ndependencies = [\"demo\"]\n\n\
+                 [[package]]\nname = \"demo\"\nversion = \"1.2.0\"\nsource = \"{registry}\"\n"
+            ),
+        )
+        .unwrap();
+        let consumer = root.join("vendor/consumer-0.5.0");
+        std::fs::create_dir_all(&consumer).unwrap();
+        std::fs::write(
+            consumer.join("Cargo.toml"),
+            "[package]\nname = \"consumer\"\nversion = \"0.5.0\"\n\n[dependencies]\ndemo = \"~1.2\"\n",
+        )
+        .unwrap();
+        for (version, lib) in [
+            ("1.2.0", "pub fn parse(input: &str) -> u32 { 0 }\n"),
+            ("1.2.1", "pub fn parse(input: &str) -> u32 { 1 }\n"),
+            (
+                "1.3.0",
+                "pub fn parse(input: &str) -> u32 { 1 }\npub fn render() {}\n",
+            ),
+        ] {
+            let src = root.join(format!("vendor/demo-{version}/src"));
+            std::fs::create_dir_all(&src).unwrap();
+            std::fs::write(
+                src.parent().unwrap().join("Cargo.toml"),
+                format!("[package]\nname = \"demo\"\nversion = \"{version}\"\n"),
+            )
+            .unwrap();
+            std::fs::write(src.join("lib.rs"), lib).unwrap();
+        }
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_patch_bump_fixing_advisory_is_safe() {
+        let dir = workspace();
+        let registry = FakeRegistry {
+            online: true,
+            versions: vec!["1.2.0", "1.2.1", "1.3.0"],
+            notes: vec![ReleaseNote {
+                version: Version::new(1, 2, 1),
+                title: "v1.2.1".to_string(),
+                body: "- Fix a panic on empty input".to_string(),
+                source: "GitHub releases".to_string(),
+            }],
+            advisories: vec![Advisory {
+                id: "RUSTSEC-2099-0001".to_string(),
+                summary: "Panic on empty input".to_string(),
+                affected: Affected::Ranges {
+                    ranges: vec![(Some(Version::new(1, 0, 0)), Some(Version::new(1, 2, 1)))],
+                    versions: Vec::new(),
+                },
+            }],
+        };
+        let tool =
+            SupplyChainCheckTool::with_registry(dir.path().to_path_buf(), Arc::new(registry));
+        let output = tool
+            .execute(json!({"action": "update_impact", "package": "demo", "version": "1.2.1"}))
+            .await
+            .unwrap()
+            .content;
+        assert!(output.contains("demo 1.2.0 → 1.2.1"), "{output}");
+        assert!(output.contains(": SAFE"), "{output}");
+        assert!(
+            output.contains("Advisories fixed:\n    RUSTSEC-2099-0001"),
+            "{output}"
+        );
+        assert!(
+            output.contains("Public API: 0 removed, 0 changed, 0 added"),
+            "{output}"
+        );
+        assert!(!output.contains("OFFLINE"), "{output}");
+    }
+
+    #[tokio::test]
+    async fn test_offline_conflict_is_breaking_and_labeled() {
+        let dir = workspace();
+        let registry = FakeRegistry {
+            online: false,
+            versions: Vec::new(),
+            notes: Vec::new(),
+            advisories: Vec::new(),
+        };
+        let tool =
+            SupplyChainCheckTool::with_registry(dir.path().to_path_buf(), Arc::new(registry));
+        let output = tool
+            .execute(json!({"action": "update_impact", "package": "demo", "version": "1.3.0"}))
+            .await
+            .unwrap()
+            .content;
+        assert!(output.contains("BREAKING"), "{output}");
+        assert!(
+            output.contains("OFFLINE: graph-and-advisory-only"),
+            "{output}"
+        );
+        assert!(output.contains("consumer 0.5.0 requires ~1.2"), "{output}");
+        // Sources are vendored, so the API diff still runs offline.
+        assert!(
+            output.contains("Public API: 0 removed, 0 changed, 1 added"),
+            "{output}"
+        );
+
+        let output = tool
+            .execute(json!({"action": "update_impact", "package": "demo"}))
+            .await
+            .unwrap()
+            .content;
+        assert!(output.contains("pass `version`"), "{output}");
+
+        let output = tool
+            .execute(json!({"action": "constraints", "package": "demo", "version": "1.3.0"}))
+            .await
+            .unwrap()
+            .content;
+        assert!(
+            output.contains("~1.2 ← consumer 0.5.0 — EXCLUDES 1.3.0"),
+            "{output}"
+        );
+        assert!(
+            output.contains("1.2 ← app (Cargo.toml) — admits 1.3.0"),
+            "{output}"
+        );
+    }
+}
@@ -0,0 +1,461 @@
+//! Registry metadata, release notes and advisories over the network.
+//!
+//! Everything the update-impact analysis fetches goes through
+//! [`RegistryClient`], so the analysis can run against a fake registry in
+//! tests and degrade cleanly when the network is unavailable.
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::advisory::{Advisory, Affected};
+use super::version::{Ecosystem, Version};
+
+const USER_AGENT: &str = "Rustant/1.0";
+
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    /// The registry could not be reached at all; analysis falls back to
+    /// local data.
+    #[error("{0} is unreachable: {1}")]
+    Unavailable(String, String),
+
+    #[error("{0} not found")]
+    NotFound(String),
+
+    #[error("Unexpected response from {0}: {1}")]
+    Invalid(String, String),
+}
+
+/// One published version of a package.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Release {
+    pub version: Version,
+    pub yanked: bool,
+}
+
+/// Registry metadata for a package.
+#[derive(Debug, Clone, Default)]
+pub struct PackageInfo {
+    pub releases: Vec<Release>,
+    /// Source repository URL, when the package links one.
+    pub repository: Option<String>,
+}
+
+impl PackageInfo {
+    /// Newest stable, non-yanked release.
+    pub fn latest(&self) -> Option<&Version> {
+        self.releases
+            .iter()
+            .filter(|r| !r.yanked && r.version.pre.is_none())
+            .map(|r| &r.version)
+            .max()
+    }
+
+    pub fn has(&self, version: &Version) -> bool {
+        self.releases.iter().any(|r| r.version == *version)
+    }
+}
+
+/// Release notes for one version, from a GitHub release or a changelog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseNote {
+    pub version: Version,
+    pub title: String,
+    pub body: String,
+    /// Where the note came from ("GitHub releases", "CHANGELOG.md").
+    pub source: String,
+}
+
+#[async_trait]
+pub trait RegistryClient: Send + Sync {
+    /// Published versions and the linked repository.
+    async fn package(&self, ecosystem: Ecosystem, name: &str)
+    -> Result<PackageInfo, RegistryError>;
+
+    /// `(dependency, requirement)` pairs declared by one published version.
+    /// Only crates.io exposes this cheaply; other registries return nothing.
+    async fn dependencies(
+        &self,
+        ecosystem: Ecosystem,
+        name: &str,
+        version: &Version,
+    ) -> Result<Vec<(String, String)>, RegistryError>;
+
+    /// GitHub releases of `repository`, newest first.
+    async fn releases(&self, repository: &str) -> Result<Vec<ReleaseNote>, RegistryError>;
+
+    /// Known advisories for the package, whichever versions they affect.
+    async fn advisories(
+        &self,
+        ecosystem: Ecosystem,
+        name: &str,
+    ) -> Result<Vec<Advisory>, RegistryError>;
+
+    /// Download and unpack a published crate under `dest`, returning the
+    /// unpacked source directory.
+    async fn download_crate(
+        &self,
+        name: &str,
+        version: &Version,
+        dest: &Path,
+    ) -> Result<PathBuf, RegistryError>;
+}
+
+/// `owner/repo` for a GitHub repository URL in any of the usual spellings.
+pub fn github_repo(url: &str) -> Option<String> {
+    let rest = url.split("github.com").nth(1)?;
+    let mut parts = rest
+        .trim_start_matches([':', '/'])
+        .split(['/', '#', '?'])
+        .filter(|p| !p.is_empty());
+    let owner = parts.next()?;
+    let repo = parts.next()?.trim_end_matches(".git");
+    Some(format!("{owner}/{repo}"))
+}
+
+/// The version a release tag names: `v1.2.3`, `serde_json-v1.0.1`,
+/// `pkg@2.0.0`, `1.2.3`.
+pub fn tag_version(tag: &str) -> Option<Version> {
+    let tag = tag.trim();
+    let candidates = [
+        tag.rsplit('@').next(),
+        tag.rsplit_once("-v").map(|(_, v)| v),
+        tag.rsplit_once('-').map(|(_, v)| v),
+        Some(tag),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .filter(|c| c.starts_with(|ch: char| ch.is_ascii_digit() || ch == 'v'))
+        .find_map(Version::parse)
+}
+
+/// Registry client for crates.io, npm, PyPI, GitHub and OSV.
+pub struct HttpRegistry {
+    client: reqwest::Client,
+    github_token: Option<String>,
+}
+
+impl HttpRegistry {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
+            .user_agent(USER_AGENT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            github_token: std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()),
+        }
+    }
+
+    async fn get_json(&self, service: &str, url: &str) -> Result<Value, RegistryError> {
+        let mut request = self.client.get(url);
+        if url.starts_with("https://api.github.com")
+            && let Some(token) = &self.github_token
+        {
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| RegistryError::Unavailable(service.to_string(), e.to_string()))?;
+        read_json(service, url, response).await
+    }
+}
+
+impl Default for HttpRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn read_json(
+    service: &str,
+    url: &str,
+    response: reqwest::Response,
+) -> Result<Value, RegistryError> {
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(RegistryError::NotFound(url.to_string()));
+    }
+    if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(RegistryError::Unavailable(
+            service.to_string(),
+            format!("HTTP {status}"),
+        ));
+    }
+    if !status.is_success() {
+        return Err(RegistryError::Invalid(
+            service.to_string(),
+            format!("HTTP {status}"),
+        ));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| RegistryError::Invalid(service.to_string(), e.to_string()))
+}
+
+fn parse_releases<'a>(versions: impl Iterator<Item = (&'a str, bool)>) -> Vec<Release> {
+    versions
+        .filter_map(|(v, yanked)| Version::parse(v).map(|version| Release { version, yanked }))
+        .collect()
+}
+
+#[async_trait]
+impl RegistryClient for HttpRegistry {
+    async fn package(
+        &self,
+        ecosystem: Ecosystem,
+        name: &str,
+    ) -> Result<PackageInfo, RegistryError> {
+        match ecosystem {
+            Ecosystem::Cargo => {
+                let url = format!("https://crates.io/api/v1/crates/{name}");
+                let body = self.get_json("crates.io", &url).await?;
+                let releases = body["versions"].as_array().into_iter().flatten().map(|v| {
+                    (
+                        v["num"].as_str().unwrap_or_default(),
+                        v["yanked"].as_bool().unwrap_or(false),
+                    )
+                });
+                Ok(PackageInfo {
+                    releases: parse_releases(releases),
+                    repository: body["crate"]["repository"].as_str().map(String::from),
+                })
+            }
+            Ecosystem::Npm => {
+                let url = format!("https://registry.npmjs.org/{}", name.replace('/', "%2F"));
+                let body = self.get_json("npm", &url).await?;
+                let releases =
+                    body["versions"]
+                        .as_object()
+                        .into_iter()
+                        .flatten()
+                        .map(|(v, meta)| {
+                            (
+                                v.as_str(),
+                                meta.get("deprecated").is_some_and(|d| d.is_string()),
+                            )
+                        });
+                let repository = match &body["repository"] {
+                    Value::String(url) => Some(url.clone()),
+                    repo => repo["url"].as_str().map(String::from),
+                };
+                Ok(PackageInfo {
+                    releases: parse_releases(releases),
+                    repository,
+                })
+            }
+            Ecosystem::PyPi => {
+                let url = format!("https://pypi.org/pypi/{name}/json");
+                let body = self.get_json("PyPI", &url).await?;
+                let releases =
+                    body["releases"]
+                        .as_object()
+                        .into_iter()
+                        .flatten()
+                        .map(|(v, files)| {
+                            let yanked = files.as_array().is_some_and(|f| {
+                                !f.is_empty() && f.iter().all(|f| f["yanked"] == true)
+                            });
+                            (v.as_str(), yanked)
+                        });
+                let urls = body["info"]["project_urls"].as_object();
+                let repository = urls
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|(_, url)| url.as_str())
+                    .find(|url| url.contains("github.com"))
+                    .or_else(|| body["info"]["home_page"].as_str())
+                    .map(String::from);
+                Ok(PackageInfo {
+                    releases: parse_releases(releases),
+                    repository,
+                })
+            }
+        }
+    }
+
+    async fn dependencies(
+        &self,
+        ecosystem: Ecosystem,
+        name: &str,
+        version: &Version,
+    ) -> Result<Vec<(String, String)>, RegistryError> {
+        if ecosystem != Ecosystem::Cargo {
+            return Ok(Vec::new());
+        }
+        let url = format!("https://crates.io/api/v1/crates/{name}/{version}/dependencies");
+        let body = self.get_json("crates.io", &url).await?;
+        Ok(body["dependencies"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|d| d["kind"].as_str() != Some("dev"))
+            .filter_map(|d| {
+                Some((
+                    d["crate_id"].as_str()?.to_string(),
+                    d["req"].as_str()?.to_string(),
+                ))
+            })
+            .collect())
+    }
+
+    async fn releases(&self, repository: &str) -> Result<Vec<ReleaseNote>, RegistryError> {
+        let Some(repo) = github_repo(repository) else {
+            return Ok(Vec::new());
+        };
+        let url = format!("https://api.github.com/repos/{repo}/releases?per_page=100");
+        let body = self.get_json("GitHub", &url).await?;
+        Ok(body
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|r| r["draft"] != true)
+            .filter_map(|r| {
+                let tag = r["tag_name"].as_str()?;
+                Some(ReleaseNote {
+                    version: tag_version(tag)?,
+                    title: r["name"]
+                        .as_str()
+                        .filter(|n| !n.is_empty())
+                        .unwrap_or(tag)
+                        .to_string(),
+                    body: r["body"].as_str().unwrap_or_default().to_string(),
+                    source: "GitHub releases".to_string(),
+                })
+            })
+            .collect())
+    }
+
+    async fn advisories(
+        &self,
+        ecosystem: Ecosystem,
+        name: &str,
+    ) -> Result<Vec<Advisory>, RegistryError> {
+        let query = json!({ "package": { "name": name, "ecosystem": ecosystem.registry() } });
+        let response = self
+            .client
+            .post("https://api.osv.dev/v1/query")
+            .json(&query)
+            .send()
+            .await
+            .map_err(|e| RegistryError::Unavailable("OSV".to_string(), e.to_string()))?;
+        let body = read_json("OSV", "https://api.osv.dev/v1/query", response).await?;
+        Ok(body["vulns"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| osv_advisory(v, name))
+            .collect())
+    }
+
+    async fn download_crate(
+        &self,
+        name: &str,
+        version: &Version,
+        dest: &Path,
+    ) -> Result<PathBuf, RegistryError> {
+        let url = format!("https://static.crates.io/crates/{name}/{name}-{version}.crate");
+        let unavailable = |e: String| RegistryError::Unavailable("crates.io".to_string(), e);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| unavailable(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(RegistryError::NotFound(url));
+        }
+        let bytes = response
+            .error_for_status()
+            .map_err(|e| unavailable(e.to_string()))?
+            .bytes()
+            .await
+            .map_err(|e| unavailable(e.to_string()))?;
+        let invalid =
+            |e: std::io::Error| RegistryError::Invalid("crates.io".to_string(), e.to_string());
+        std::fs::create_dir_all(dest).map_err(invalid)?;
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(&bytes[..]));
+        for entry in archive.entries().map_err(invalid)? {
+            // unpack_in refuses entries that would escape `dest`.
+            entry.map_err(invalid)?.unpack_in(dest).map_err(invalid)?;
+        }
+        Ok(dest.join(format!("{name}-{version}")))
+    }
+}
+
+/// Convert one OSV record into an [`Advisory`] for `package`.
+fn osv_advisory(vuln: &Value, package: &str) -> Option<Advisory> {
+    let id = vuln["id"].as_str()?.to_string();
+    let mut ranges = Vec::new();
+    let mut versions = Vec::new();
+    for affected in vuln["affected"].as_array().into_iter().flatten() {
+        if affected["package"]["name"].as_str() != Some(package) {
+            continue;
+        }
+        versions.extend(
+            affected["versions"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str().and_then(Version::parse)),
+        );
+        for range in affected["ranges"].as_array().into_iter().flatten() {
+            if range["type"].as_str() == Some("GIT") {
+                continue;
+            }
+            let mut introduced = None;
+            for event in range["events"].as_array().into_iter().flatten() {
+                if let Some(v) = event["introduced"].as_str() {
+                    introduced = Some(Version::parse(v).unwrap_or_else(|| Version::new(0, 0, 0)));
+                } else if let Some(v) = event["fixed"].as_str() {
+                    ranges.push((introduced.take(), Version::parse(v)));
+                }
+            }
+            if introduced.is_some() {
+                ranges.push((introduced, None));
+            }
+        }
+    }
+    let summary = vuln["summary"]
+        .as_str()
+        .or_else(|| vuln["details"].as_str())
+        .unwrap_or_default()
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    Some(Advisory {
+        id,
+        summary,
+        affected: Affected::Ranges { ranges, versions },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_and_repository_parsing() {
+        assert_eq!(tag_version("v1.2.3"), Version::parse("1.2.3"));
+        assert_eq!(
+            tag_version("serde_json-v1.0.108"),
+            Version::parse("1.0.108")
+        );
+        assert_eq!(tag_version("@scope/pkg@2.0.0"), Version::parse("2.0.0"));
+        assert_eq!(tag_version("tokio-1.35.0"), Version::parse("1.35.0"));
+        assert_eq!(tag_version("nightly"), None);
+        assert_eq!(
+            github_repo("git+https://github.com/serde-rs/json.git").as_deref(),
+            Some("serde-rs/json")
+        );
+        assert_eq!(github_repo("https://gitlab.com/a/b"), None);
+    }
+}
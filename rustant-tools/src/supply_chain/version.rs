@@ -0,0 +1,461 @@
+//! Versions and version requirements for Cargo, npm and PyPI.
+//!
+//! Only the subset needed to decide whether a requirement admits a version:
+//! Cargo's comma-separated comparators (bare versions are caret
+//! requirements), npm ranges (`||`, hyphen ranges, x-ranges; bare versions
+//! are exact) and PEP 440 specifiers (`==`, `!=`, `~=`, `===`, wildcards).
+//! Pre-releases only satisfy requirements that mention a pre-release.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A package ecosystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    PyPi,
+}
+
+impl Ecosystem {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "cargo" | "crates" | "crates.io" | "rust" => Some(Self::Cargo),
+            "npm" | "node" | "javascript" => Some(Self::Npm),
+            "pypi" | "pip" | "python" => Some(Self::PyPi),
+            _ => None,
+        }
+    }
+
+    /// Registry name, also the ecosystem name used by OSV.
+    pub fn registry(self) -> &'static str {
+        match self {
+            Self::Cargo => "crates.io",
+            Self::Npm => "npm",
+            Self::PyPi => "PyPI",
+        }
+    }
+}
+
+/// A released version. Pre-release tags (and PEP 440 `a`/`b`/`rc`/`dev`
+/// suffixes) sort before the release.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Option<String>,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: None,
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let partial = Partial::parse(text)?;
+        Some(Self {
+            major: partial.major?,
+            minor: partial.minor.unwrap_or(0),
+            patch: partial.patch.unwrap_or(0),
+            pre: partial.pre,
+        })
+    }
+
+    fn triple(&self) -> (u64, u64, u64) {
+        (self.major, self.minor, self.patch)
+    }
+
+    /// Whether moving from `self` to `to` stays within the ecosystem's notion
+    /// of a compatible release (same leftmost non-zero component for Cargo
+    /// and npm, same major for PyPI).
+    pub fn is_compatible_with(&self, to: &Version, ecosystem: Ecosystem) -> bool {
+        if self.major != to.major {
+            return false;
+        }
+        match ecosystem {
+            Ecosystem::PyPi => true,
+            Ecosystem::Cargo | Ecosystem::Npm if self.major > 0 => true,
+            Ecosystem::Cargo | Ecosystem::Npm if self.minor > 0 => self.minor == to.minor,
+            Ecosystem::Cargo | Ecosystem::Npm => self.minor == to.minor && self.patch == to.patch,
+        }
+    }
+
+    /// "major", "minor" or "patch": the highest component that changed.
+    pub fn bump_kind(&self, to: &Version) -> &'static str {
+        if self.major != to.major {
+            "major"
+        } else if self.minor != to.minor {
+            "minor"
+        } else {
+            "patch"
+        }
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.triple()
+            .cmp(&other.triple())
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{pre}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A possibly partial version inside a requirement: `1`, `1.2`, `1.2.*`.
+#[derive(Debug, Clone, PartialEq)]
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Option<String>,
+}
+
+impl Partial {
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().trim_start_matches(['v', 'V', '=']);
+        let text = text.split('+').next()?.trim();
+        if text.is_empty() || matches!(text, "*" | "x" | "X") {
+            return Some(Self {
+                major: None,
+                minor: None,
+                patch: None,
+                pre: None,
+            });
+        }
+        let (core, mut pre) = match text.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (text, None),
+        };
+        let mut parts = [None; 3];
+        for (i, component) in core.split('.').enumerate() {
+            if matches!(component, "*" | "x" | "X") {
+                break;
+            }
+            let digits = component.len()
+                - component
+                    .trim_start_matches(|c: char| c.is_ascii_digit())
+                    .len();
+            if digits == 0 {
+                return None;
+            }
+            let number: u64 = component[..digits].parse().ok()?;
+            let rest = &component[digits..];
+            if i < 3 {
+                parts[i] = Some(number);
+            }
+            if !rest.is_empty() {
+                // PEP 440 suffixes: 1.0rc1, 2.0.0a3, 1.2.dev4; post-releases
+                // are releases.
+                if !rest.starts_with("post") && pre.is_none() {
+                    pre = Some(rest.trim_start_matches(['.', '_']).to_string());
+                }
+                break;
+            }
+        }
+        let major = parts[0]?;
+        Some(Self {
+            major: Some(major),
+            minor: parts[1],
+            patch: parts[2],
+            pre,
+        })
+    }
+
+    fn floor(&self) -> Version {
+        Version {
+            major: self.major.unwrap_or(0),
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            pre: self.pre.clone(),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.patch.is_some()
+    }
+
+    /// Exclusive upper bound of everything this partial version names.
+    fn next(&self) -> Option<Version> {
+        match (self.major, self.minor, self.patch) {
+            (None, ..) => None,
+            (Some(major), None, _) => Some(Version::new(major + 1, 0, 0)),
+            (Some(major), Some(minor), None) => Some(Version::new(major, minor + 1, 0)),
+            (Some(major), Some(minor), Some(patch)) => Some(Version::new(major, minor, patch + 1)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Exact,
+    NotEqual,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+    /// PEP 440 `~=`.
+    Compatible,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparator {
+    op: Op,
+    version: Partial,
+}
+
+impl Comparator {
+    fn parse(token: &str, bare: Op) -> Option<Self> {
+        let token = token.trim();
+        let ops = [
+            ("===", Op::Exact),
+            ("==", Op::Exact),
+            ("!=", Op::NotEqual),
+            ("~=", Op::Compatible),
+            (">=", Op::GreaterEq),
+            ("<=", Op::LessEq),
+            (">", Op::Greater),
+            ("<", Op::Less),
+            ("=", Op::Exact),
+            ("^", Op::Caret),
+            ("~", Op::Tilde),
+        ];
+        let (op, rest) = ops
+            .iter()
+            .find_map(|(prefix, op)| token.strip_prefix(prefix).map(|rest| (*op, rest)))
+            .unwrap_or((bare, token));
+        let version = Partial::parse(rest)?;
+        Some(Self { op, version })
+    }
+
+    /// Bounds as (lower, lower inclusive, upper exclusive).
+    fn bounds(&self) -> (Option<Version>, bool, Option<Version>) {
+        let v = &self.version;
+        let floor = v.floor();
+        if v.major.is_none() {
+            return (None, true, None);
+        }
+        match self.op {
+            Op::Exact | Op::NotEqual if v.is_full() => (Some(floor.clone()), true, None),
+            Op::Exact | Op::NotEqual => (Some(floor), true, v.next()),
+            Op::Greater if v.is_full() => (Some(floor), false, None),
+            Op::Greater => (v.next(), true, None),
+            Op::GreaterEq => (Some(floor), true, None),
+            Op::Less => (None, true, Some(floor)),
+            Op::LessEq if v.is_full() => (None, true, None),
+            Op::LessEq => (None, true, v.next()),
+            Op::Tilde => {
+                let major = v.major.unwrap_or(0);
+                let upper = match v.minor {
+                    Some(minor) => Version::new(major, minor + 1, 0),
+                    None => Version::new(major + 1, 0, 0),
+                };
+                (Some(floor), true, Some(upper))
+            }
+            Op::Caret => {
+                let (major, minor) = (v.major.unwrap_or(0), v.minor.unwrap_or(0));
+                let upper = if major > 0 || v.minor.is_none() {
+                    Version::new(major + 1, 0, 0)
+                } else if minor > 0 || v.patch.is_none() {
+                    Version::new(0, minor + 1, 0)
+                } else {
+                    Version::new(0, 0, v.patch.unwrap_or(0) + 1)
+                };
+                (Some(floor), true, Some(upper))
+            }
+            Op::Compatible => {
+                let major = v.major.unwrap_or(0);
+                let upper = match (v.minor, v.patch) {
+                    (Some(minor), Some(_)) => Version::new(major, minor + 1, 0),
+                    _ => Version::new(major + 1, 0, 0),
+                };
+                (Some(floor), true, Some(upper))
+            }
+        }
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        let inside = match self.op {
+            Op::Exact | Op::NotEqual if self.version.is_full() => *version == self.version.floor(),
+            Op::LessEq if self.version.is_full() => *version <= self.version.floor(),
+            _ => {
+                let (lower, inclusive, upper) = self.bounds();
+                let above = lower.is_none_or(|lower| {
+                    if inclusive {
+                        *version >= lower
+                    } else {
+                        *version > lower
+                    }
+                });
+                above && upper.is_none_or(|upper| *version < upper)
+            }
+        };
+        if self.op == Op::NotEqual {
+            !inside
+        } else {
+            inside
+        }
+    }
+}
+
+/// A version requirement: alternatives (`||`) of comparator sets that must
+/// all match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    alternatives: Vec<Vec<Comparator>>,
+    mentions_pre: bool,
+}
+
+impl Requirement {
+    /// Parse a requirement in `ecosystem`'s syntax. `None` for forms that are
+    /// not version ranges (git URLs, paths, dist-tags).
+    pub fn parse(ecosystem: Ecosystem, text: &str) -> Option<Self> {
+        let text = text.trim();
+        let alternatives = match ecosystem {
+            Ecosystem::Cargo => vec![parse_set(text.split(','), Op::Caret)?],
+            Ecosystem::PyPi => vec![parse_set(text.split(','), Op::Exact)?],
+            Ecosystem::Npm => text
+                .split("||")
+                .map(parse_npm_range)
+                .collect::<Option<Vec<_>>>()?,
+        };
+        Some(Self {
+            mentions_pre: alternatives
+                .iter()
+                .flatten()
+                .any(|c| c.version.pre.is_some()),
+            alternatives,
+        })
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        if version.pre.is_some() && !self.mentions_pre {
+            return false;
+        }
+        self.alternatives
+            .iter()
+            .any(|set| set.iter().all(|c| c.matches(version)))
+    }
+}
+
+fn parse_set<'a>(tokens: impl Iterator<Item = &'a str>, bare: Op) -> Option<Vec<Comparator>> {
+    tokens
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|t| Comparator::parse(t, bare))
+        .collect()
+}
+
+fn parse_npm_range(range: &str) -> Option<Vec<Comparator>> {
+    let range = range.trim();
+    if range.contains(':') || range.contains('/') {
+        return None;
+    }
+    if let Some((low, high)) = range.split_once(" - ") {
+        return Some(vec![
+            Comparator::parse(&format!(">={}", low.trim()), Op::Exact)?,
+            Comparator::parse(&format!("<={}", high.trim()), Op::Exact)?,
+        ]);
+    }
+    // Glue operators written apart from their version (">= 1.2").
+    let mut tokens = Vec::new();
+    let mut pending = String::new();
+    for token in range.split_whitespace() {
+        if token.chars().all(|c| "<>=~^".contains(c)) {
+            pending.push_str(token);
+        } else {
+            tokens.push(format!("{pending}{token}"));
+            pending.clear();
+        }
+    }
+    if tokens.is_empty() {
+        return Some(Vec::new());
+    }
+    if tokens.iter().any(|t| {
+        t.chars()
+            .any(|c| c.is_ascii_alphabetic() && !"vxX".contains(c))
+            && !t.contains('-')
+    }) {
+        // Dist-tags such as "latest" or "next".
+        return None;
+    }
+    tokens
+        .iter()
+        .map(|t| Comparator::parse(t, Op::Exact))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn admits(ecosystem: Ecosystem, req: &str, version: &str) -> bool {
+        Requirement::parse(ecosystem, req)
+            .unwrap_or_else(|| panic!("unparseable requirement {req}"))
+            .matches(&Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn test_cargo_requirements() {
+        assert!(admits(Ecosystem::Cargo, "1.0.100", "1.4.0"));
+        assert!(!admits(Ecosystem::Cargo, "1.0.100", "2.0.0"));
+        assert!(admits(Ecosystem::Cargo, "0.3", "0.3.9"));
+        assert!(!admits(Ecosystem::Cargo, "0.3", "0.4.0"));
+        assert!(!admits(Ecosystem::Cargo, "^0.0.3", "0.0.4"));
+        assert!(admits(Ecosystem::Cargo, "~1.2", "1.2.7"));
+        assert!(!admits(Ecosystem::Cargo, "~1.2", "1.3.0"));
+        assert!(!admits(Ecosystem::Cargo, "=1.0.190", "1.0.200"));
+        assert!(admits(Ecosystem::Cargo, ">=1.2, <1.5", "1.4.9"));
+        assert!(!admits(Ecosystem::Cargo, ">=1.2, <1.5", "1.5.0"));
+        assert!(admits(Ecosystem::Cargo, "1.*", "1.9.0"));
+        assert!(!admits(Ecosystem::Cargo, "1", "1.1.0-beta.1"));
+    }
+
+    #[test]
+    fn test_npm_and_pypi_requirements() {
+        assert!(admits(Ecosystem::Npm, "^4.17.0 || ^5.0.0", "5.2.1"));
+        assert!(!admits(Ecosystem::Npm, "4.17.21", "4.17.22"));
+        assert!(admits(Ecosystem::Npm, "1.2.x", "1.2.9"));
+        assert!(admits(Ecosystem::Npm, ">= 2.1.2 < 3", "2.9.0"));
+        assert!(admits(Ecosystem::Npm, "1.0.0 - 2.3", "2.3.9"));
+        assert!(!admits(Ecosystem::Npm, "1.0.0 - 2.3", "2.4.0"));
+        assert!(Requirement::parse(Ecosystem::Npm, "latest").is_none());
+
+        assert!(admits(Ecosystem::PyPi, ">=2.28,<3", "2.31.0"));
+        assert!(!admits(Ecosystem::PyPi, "~=2.28", "3.0.0"));
+        assert!(admits(Ecosystem::PyPi, "~=1.4.2", "1.4.9"));
+        assert!(!admits(Ecosystem::PyPi, "~=1.4.2", "1.5.0"));
+        assert!(admits(Ecosystem::PyPi, "==1.4.*", "1.4.2"));
+        assert!(!admits(Ecosystem::PyPi, "!=1.4.2", "1.4.2"));
+        assert!(Version::parse("2.0rc1").unwrap() < Version::parse("2.0").unwrap());
+    }
+}
@@ -405,6 +405,15 @@ impl SystemMonitorTool {
             };
 
             let svc = &mut state.services[idx];
+            if svc.status != new_status {
+                rustant_core::incidents::record_alert_change(
+                    &self.workspace,
+                    &format!(
+                        "{} #{}: {} → {} ({})",
+                        svc.name, svc.id, svc.status, new_status, detail
+                    ),
+                );
+            }
             svc.status = new_status.clone();
             svc.last_checked = Some(Utc::now());
             svc.response_time_ms = Some(elapsed_ms);
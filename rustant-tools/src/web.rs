@@ -2,13 +2,17 @@
 //!
 //! Lightweight web access tools that work without browser automation.
 //! - `web_search`: Search the web using DuckDuckGo instant answers (privacy-first).
-//! - `web_fetch`: Fetch a URL and extract readable text content.
+//! - `web_fetch`: Fetch a URL and extract its main content as markdown.
 //! - `document_read`: Read PDF and text documents from the local filesystem.
 
+use crate::browser::BrowserToolContext;
 use crate::registry::Tool;
+use crate::web_extract::extract_article;
+use crate::web_politeness::{CachedResponse, FetchState, RobotsRules};
 use async_trait::async_trait;
+use rustant_core::config::WebFetchConfig;
 use rustant_core::error::ToolError;
-use rustant_core::types::{RiskLevel, ToolOutput};
+use rustant_core::types::{RiskLevel, ToolErrorKind, ToolFailure, ToolOutput};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
@@ -96,14 +100,13 @@ impl Tool for WebSearchTool {
             urlencoding::encode(query)
         );
 
-        let response = client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "web_search".into(),
-                message: format!("Search request failed: {}", e),
-            })?;
+        let response = client.get(&url).send().await.map_err(|e| {
+            ToolError::failed(
+                "web_search",
+                format!("Search request failed: {}", e),
+                ToolFailure::from_reqwest(&e),
+            )
+        })?;
 
         let body: serde_json::Value =
             response
@@ -181,17 +184,305 @@ impl Tool for WebSearchTool {
 // WebFetchTool
 // ---------------------------------------------------------------------------
 
-/// Fetch a URL and extract readable text content.
+/// Fetch a URL and extract its main content as markdown.
 ///
-/// Strips HTML tags and returns clean text. Much lighter than browser
-/// automation — no Chrome required.
-#[derive(Default)]
-pub struct WebFetchTool;
+/// HTML goes through readability-style extraction (see [`crate::web_extract`])
+/// that keeps the article body and drops navigation and page chrome. Requests
+/// respect robots.txt, are spaced per host, and revalidate cached responses
+/// with ETag / Last-Modified (see [`crate::web_politeness`]). Responses that
+/// are too large or not text are refused before they reach the context.
+///
+/// Much lighter than browser automation — no Chrome required. When
+/// `[tools.web_fetch] js_render` is on and a browser session is attached with
+/// [`WebFetchTool::with_browser`], pages whose static HTML is near-empty are
+/// rendered in a background tab instead.
+pub struct WebFetchTool {
+    config: Option<WebFetchConfig>,
+    browser: Option<BrowserToolContext>,
+}
+
+impl Default for WebFetchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl WebFetchTool {
+    /// A tool that reads `[tools.web_fetch]` from the global config on each call.
     pub fn new() -> Self {
-        Self
+        Self {
+            config: None,
+            browser: None,
+        }
+    }
+
+    /// Use `config` instead of loading `[tools.web_fetch]`.
+    pub fn with_config(mut self, config: WebFetchConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Attach a browser session for the JavaScript rendering fallback.
+    pub fn with_browser(mut self, browser: BrowserToolContext) -> Self {
+        self.browser = Some(browser);
+        self
+    }
+
+    fn config(&self) -> WebFetchConfig {
+        self.config.clone().unwrap_or_else(|| {
+            rustant_core::config::load_config(None, None)
+                .map(|c| c.tools.web_fetch)
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// Outcome of reading a response body under the size and type guards.
+enum FetchedBody {
+    Accepted(CachedResponse),
+    Refused(String),
+}
+
+/// Read a successful response, refusing non-text content types and bodies
+/// over `max_response_bytes` without buffering more than the limit.
+async fn read_guarded_body(
+    mut response: reqwest::Response,
+    url: &str,
+    config: &WebFetchConfig,
+) -> Result<FetchedBody, ToolError> {
+    let header = |name: reqwest::header::HeaderName| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let content_type = header(reqwest::header::CONTENT_TYPE).unwrap_or_default();
+    let etag = header(reqwest::header::ETAG);
+    let last_modified = header(reqwest::header::LAST_MODIFIED);
+    let final_url = response.url().to_string();
+    let max = config.max_response_bytes;
+
+    if !content_type.is_empty() && !config.accepts_content_type(&content_type) {
+        return Ok(FetchedBody::Refused(format!(
+            "Refusing to fetch {}: content type '{}' is not a text format. \
+             Allowed types are set in [tools.web_fetch] allowed_content_types.",
+            url, content_type
+        )));
+    }
+    if let Some(length) = response.content_length()
+        && length > max as u64
+    {
+        return Ok(FetchedBody::Refused(format!(
+            "Refusing to fetch {}: response is {} bytes, over the {}-byte limit \
+             ([tools.web_fetch] max_response_bytes).",
+            url, length, max
+        )));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| {
+        ToolError::failed(
+            "web_fetch",
+            format!("Failed to read response body: {}", e),
+            ToolFailure::from_reqwest(&e),
+        )
+    })? {
+        if body.len() + chunk.len() > max {
+            return Ok(FetchedBody::Refused(format!(
+                "Refusing to fetch {}: response exceeded the {}-byte limit \
+                 ([tools.web_fetch] max_response_bytes).",
+                url, max
+            )));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    if content_type.is_empty() && body.contains(&0) {
+        return Ok(FetchedBody::Refused(format!(
+            "Refusing to fetch {}: response has no content type and looks binary.",
+            url
+        )));
+    }
+
+    Ok(FetchedBody::Accepted(CachedResponse {
+        body: String::from_utf8_lossy(&body).into_owned(),
+        content_type,
+        final_url,
+        etag,
+        last_modified,
+    }))
+}
+
+/// robots.txt rules for `url`'s origin, fetched once per [`ROBOTS_TTL`].
+/// A missing file (4xx) or unreachable host allows everything; a server
+/// error disallows everything.
+///
+/// [`ROBOTS_TTL`]: crate::web_politeness::ROBOTS_TTL
+async fn robots_rules(
+    client: &reqwest::Client,
+    url: &reqwest::Url,
+    user_agent: &str,
+    state: &FetchState,
+) -> RobotsRules {
+    let origin = url.origin().ascii_serialization();
+    if let Some(rules) = state.robots(&origin) {
+        return rules;
+    }
+    let rules = match client.get(format!("{}/robots.txt", origin)).send().await {
+        Ok(response) if response.status().is_success() => match response.text().await {
+            Ok(body) => RobotsRules::parse(&body, user_agent),
+            Err(_) => RobotsRules::allow_all(),
+        },
+        Ok(response) if response.status().is_server_error() => RobotsRules::disallow_all(),
+        _ => RobotsRules::allow_all(),
+    };
+    state.store_robots(&origin, rules.clone());
+    rules
+}
+
+/// Outcome of [`polite_fetch`].
+pub(crate) enum PoliteFetch {
+    /// The response, fresh or revalidated from the shared cache, with notes
+    /// such as "Cache: revalidated" for the output header.
+    Fetched {
+        response: CachedResponse,
+        notes: Vec<String>,
+    },
+    /// Not fetched: robots.txt or a content type or size guard refused it.
+    Refused(String),
+    /// The server answered with a status other than success.
+    Status {
+        status: reqwest::StatusCode,
+        retry_after: Option<u64>,
+    },
+}
+
+/// Fetch `url` the way `web_fetch` does: robots.txt (unless
+/// `ignore_robots`), per-host request spacing, ETag / Last-Modified
+/// revalidation through the shared cache, and the size and content type
+/// guards. `tool` names the caller in errors.
+pub(crate) async fn polite_fetch(
+    tool: &str,
+    url: &str,
+    parsed: &reqwest::Url,
+    config: &WebFetchConfig,
+    ignore_robots: bool,
+) -> Result<PoliteFetch, ToolError> {
+    let state = FetchState::shared();
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .user_agent(config.user_agent.as_str())
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .build()
+        .map_err(|e| ToolError::ExecutionFailed {
+            name: tool.into(),
+            message: format!("Failed to create HTTP client: {}", e),
+        })?;
+
+    // Metadata lines after "Content from ...", e.g. "Cache: ...".
+    let mut notes: Vec<String> = Vec::new();
+    let mut interval = Duration::from_millis(config.min_host_interval_ms);
+    if ignore_robots {
+        notes.push("Robots: ignored (ignore_robots)".into());
+    } else if config.respect_robots {
+        let rules = robots_rules(&client, parsed, &config.user_agent, &state).await;
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+        if !rules.is_allowed(&path) {
+            return Ok(PoliteFetch::Refused(format!(
+                "robots.txt for {} disallows fetching {} for user agent '{}'. \
+                 Pass ignore_robots: true only if the user explicitly asks to fetch it anyway.",
+                parsed.origin().ascii_serialization(),
+                url,
+                config.user_agent
+            )));
+        }
+        if let Some(delay) = rules.crawl_delay() {
+            interval = interval.max(delay);
+        }
     }
+
+    let host = format!(
+        "{}:{}",
+        parsed.host_str().unwrap_or(""),
+        parsed.port_or_known_default().unwrap_or(0)
+    );
+    let wait = state.reserve_slot(&host, interval);
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+
+    let cached = state.cached(url);
+    let mut request = client.get(url);
+    if let Some(cached) = &cached {
+        if let Some(etag) = &cached.etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request.send().await.map_err(|e| {
+        ToolError::failed(
+            tool,
+            format!("Fetch failed: {}", e),
+            ToolFailure::from_reqwest(&e),
+        )
+    })?;
+
+    let status = response.status();
+    let fetched = match cached {
+        Some(cached) if status == reqwest::StatusCode::NOT_MODIFIED => {
+            notes.push("Cache: revalidated (304 Not Modified)".into());
+            cached
+        }
+        _ if !status.is_success() => {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok());
+            return Ok(PoliteFetch::Status {
+                status,
+                retry_after,
+            });
+        }
+        _ => match read_guarded_body(response, url, config).await? {
+            FetchedBody::Accepted(fetched) => {
+                state.store_response(url, fetched.clone(), config.cache_entries);
+                fetched
+            }
+            FetchedBody::Refused(reason) => return Ok(PoliteFetch::Refused(reason)),
+        },
+    };
+    Ok(PoliteFetch::Fetched {
+        response: fetched,
+        notes,
+    })
+}
+
+/// Load `url` in a new browser tab and return the rendered HTML, restoring
+/// the previously active tab afterwards.
+async fn render_in_browser(browser: &BrowserToolContext, url: &str) -> Result<String, String> {
+    browser.security.check_url(url)?;
+    let client = &browser.client;
+    let previous = client.active_tab_id().await.ok();
+    let tab = client.new_tab(url).await.map_err(|e| e.to_string())?;
+    let rendered = async {
+        client.switch_tab(&tab).await?;
+        // Best effort: a page without a body still has HTML worth reading.
+        let _ = client.wait_for_selector("body", 5000).await;
+        client.get_html().await
+    }
+    .await;
+    let _ = client.close_tab(&tab).await;
+    if let Some(previous) = previous {
+        let _ = client.switch_tab(&previous).await;
+    }
+    rendered.map_err(|e| e.to_string())
 }
 
 #[async_trait]
@@ -201,9 +492,10 @@ impl Tool for WebFetchTool {
     }
 
     fn description(&self) -> &str {
-        "Fetch a web page URL and extract its text content. Returns the readable text \
-         from the page, stripped of HTML. Use this to read documentation, articles, or \
-         API references from the web."
+        "Fetch a web page URL and extract its main content as markdown, with title, \
+         byline and publish date when available. Navigation and page chrome are dropped. \
+         Use this to read documentation, articles, or API references from the web. \
+         Set raw for the original HTML. Respects robots.txt unless ignore_robots is set."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -218,6 +510,16 @@ impl Tool for WebFetchTool {
                     "type": "integer",
                     "description": "Maximum characters of content to return (default: 5000)",
                     "default": 5000
+                },
+                "raw": {
+                    "type": "boolean",
+                    "description": "Return the original response body without extraction (default: false)",
+                    "default": false
+                },
+                "ignore_robots": {
+                    "type": "boolean",
+                    "description": "Fetch even if robots.txt disallows it. Only when the user explicitly asks (default: false)",
+                    "default": false
                 }
             },
             "required": ["url"]
@@ -244,6 +546,11 @@ impl Tool for WebFetchTool {
             .get("max_length")
             .and_then(|v| v.as_u64())
             .unwrap_or(5000) as usize;
+        let raw = args.get("raw").and_then(|v| v.as_bool()).unwrap_or(false);
+        let ignore_robots = args
+            .get("ignore_robots")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
 
         // Validate URL
         if !url.starts_with("http://") && !url.starts_with("https://") {
@@ -252,70 +559,103 @@ impl Tool for WebFetchTool {
                 reason: "URL must start with http:// or https://".into(),
             });
         }
+        let parsed = reqwest::Url::parse(url).map_err(|e| ToolError::InvalidArguments {
+            name: "web_fetch".into(),
+            reason: format!("Invalid URL: {}", e),
+        })?;
 
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(15))
-            .user_agent("Rustant/1.0")
-            .redirect(reqwest::redirect::Policy::limited(5))
-            .build()
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "web_fetch".into(),
-                message: format!("Failed to create HTTP client: {}", e),
-            })?;
-
-        let response = client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "web_fetch".into(),
-                message: format!("Fetch failed: {}", e),
-            })?;
+        let config = self.config();
+        let (fetched, mut notes) =
+            match polite_fetch("web_fetch", url, &parsed, &config, ignore_robots).await? {
+                PoliteFetch::Fetched { response, notes } => (response, notes),
+                PoliteFetch::Refused(reason) => return Ok(ToolOutput::text(reason)),
+                PoliteFetch::Status {
+                    status,
+                    retry_after,
+                } => {
+                    let text = format!("HTTP {} for URL: {}", status, url);
+                    return Ok(
+                        match ToolFailure::from_http_status(status.as_u16(), retry_after) {
+                            Some(failure) => ToolOutput::failure(text, failure),
+                            None => ToolOutput::text(text),
+                        },
+                    );
+                }
+            };
 
-        let status = response.status();
-        if !status.is_success() {
-            return Ok(ToolOutput::text(format!(
-                "HTTP {} for URL: {}",
-                status, url
-            )));
+        let is_html = fetched.content_type.contains("text/html")
+            || fetched.content_type.contains("application/xhtml")
+            || (fetched.content_type.is_empty() && fetched.body.trim_start().starts_with('<'));
+        let mut header = vec![format!("Content from {}:", url)];
+        if fetched.final_url != url && fetched.final_url != parsed.as_str() {
+            header.push(format!("Redirected to: {}", fetched.final_url));
         }
 
-        let content_type = response
-            .headers()
-            .get("content-type")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("")
+        let (text, extraction) = if raw {
+            (fetched.body, "raw (original response body)".to_string())
+        } else if is_html {
+            let base = reqwest::Url::parse(&fetched.final_url).ok();
+            let mut article = extract_article(&fetched.body, base.as_ref());
+            let mut extraction = if article.main_content {
+                "readability (main content)"
+            } else {
+                "full page (no main content found)"
+            }
             .to_string();
 
-        let body = response
-            .text()
-            .await
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "web_fetch".into(),
-                message: format!("Failed to read response body: {}", e),
-            })?;
+            let near_empty = article.markdown.chars().count() < config.min_content_chars;
+            if near_empty
+                && config.js_render
+                && let Some(browser) = &self.browser
+            {
+                match render_in_browser(browser, &fetched.final_url).await {
+                    Ok(html) => {
+                        let rendered = extract_article(&html, base.as_ref());
+                        if rendered.markdown.chars().count() > article.markdown.chars().count() {
+                            extraction = if rendered.main_content {
+                                "readability (browser-rendered)"
+                            } else {
+                                "full page (browser-rendered, no main content found)"
+                            }
+                            .to_string();
+                            article = rendered;
+                        } else {
+                            notes.push("Render: browser found no more content".into());
+                        }
+                    }
+                    Err(e) => notes.push(format!("Render: browser fallback failed: {}", e)),
+                }
+            }
 
-        // Extract text from HTML
-        let text =
-            if content_type.contains("text/html") || content_type.contains("application/xhtml") {
-                extract_text_from_html(&body)
-            } else {
-                // Plain text or other formats — return as-is
-                body
-            };
+            for (label, value) in [
+                ("Title", &article.title),
+                ("Byline", &article.byline),
+                ("Published", &article.published),
+                ("Site", &article.site_name),
+            ] {
+                if let Some(value) = value {
+                    header.push(format!("{}: {}", label, value));
+                }
+            }
+            (article.markdown, extraction)
+        } else {
+            (fetched.body, "plain text".to_string())
+        };
+        header.push(format!("Extraction: {}", extraction));
+        header.extend(notes);
 
         // Truncate if needed
-        let text = if text.len() > max_length {
-            format!(
+        let text = match text.char_indices().nth(max_length) {
+            Some((cut, _)) => format!(
                 "{}...\n\n[Truncated at {} characters. Use max_length to see more.]",
-                &text[..max_length],
+                &text[..cut],
                 max_length
-            )
-        } else {
-            text
+            ),
+            None if text.trim().is_empty() => "(no readable content)".to_string(),
+            None => text,
         };
 
-        let content = format!("Content from {}:\n\n{}", url, text);
+        let content = format!("{}\n\n{}", header.join("\n"), text);
 
         Ok(ToolOutput::text(content))
     }
@@ -436,24 +776,53 @@ impl DocumentReadTool {
             self.workspace.join(path)
         };
 
-        let canonical = resolved
-            .canonicalize()
-            .map_err(|e| ToolError::ExecutionFailed {
-                name: "document_read".into(),
-                message: format!("Path resolution failed: {}", e),
-            })?;
+        let canonical = resolved.canonicalize().map_err(|e| {
+            ToolError::failed(
+                "document_read",
+                format!("Path resolution failed: {}", e),
+                ToolFailure::from_io(&e),
+            )
+        })?;
 
         // Allow reading outside workspace for documents (e.g., ~/Downloads/*.pdf)
         // but still validate the path exists
         if !canonical.exists() {
-            return Err(ToolError::ExecutionFailed {
-                name: "document_read".into(),
-                message: format!("File not found: {}", path),
-            });
+            return Err(ToolError::failed(
+                "document_read",
+                format!("File not found: {}", path),
+                ToolFailure::new(ToolErrorKind::NotFound),
+            ));
         }
 
         Ok(canonical)
     }
+
+    /// Resolve an attachment handle to its stored file and the extension
+    /// matching its MIME type.
+    fn resolve_attachment(&self, id: &str) -> Result<(PathBuf, String), ToolError> {
+        let (handle, path) =
+            rustant_core::attachments::resolve(id).map_err(|e| ToolError::InvalidArguments {
+                name: "document_read".into(),
+                reason: e.to_string(),
+            })?;
+        let extension = match handle.mime_type.split(';').next().unwrap_or("").trim() {
+            "text/markdown" => "md",
+            "text/csv" => "csv",
+            "text/html" => "html",
+            "application/json" => "json",
+            "application/xml" | "text/xml" => "xml",
+            "application/toml" => "toml",
+            "application/yaml" | "text/yaml" => "yaml",
+            mime if mime.starts_with("text/") => "txt",
+            other => {
+                return Err(ToolError::InvalidArguments {
+                    name: "document_read".into(),
+                    reason: format!("attachment {} is {}, not a text document", id, other),
+                });
+            }
+        };
+        Ok((path, extension.to_string()))
+    }
 }
 
 #[async_trait]
@@ -474,7 +843,7 @@ impl Tool for DocumentReadTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "Path to the document file (relative to workspace or absolute)"
+                    "description": "Path to the document file (relative to workspace or absolute), or an attachment handle (att-...)"
                 },
                 "max_length": {
                     "type": "integer",
@@ -507,13 +876,17 @@ impl Tool for DocumentReadTool {
             .and_then(|v| v.as_u64())
             .unwrap_or(10000) as usize;
 
-        let path = self.resolve_path(path_str)?;
-
-        let extension = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_lowercase();
+        let (path, extension) = if rustant_core::attachments::is_handle(path_str) {
+            self.resolve_attachment(path_str)?
+        } else {
+            let path = self.resolve_path(path_str)?;
+            let extension = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("")
+                .to_lowercase();
+            (path, extension)
+        };
 
         // Validate supported extensions
         let supported = [
@@ -551,9 +924,12 @@ impl Tool for DocumentReadTool {
         }
 
         // Read file
-        let content = std::fs::read_to_string(&path).map_err(|e| ToolError::ExecutionFailed {
-            name: "document_read".into(),
-            message: format!("Failed to read file: {}", e),
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            ToolError::failed(
+                "document_read",
+                format!("Failed to read file: {}", e),
+                ToolFailure::from_io(&e),
+            )
         })?;
 
         // For HTML files, extract text
@@ -924,4 +1300,290 @@ mod tests {
         let required = schema["required"].as_array().unwrap();
         assert!(required.contains(&serde_json::json!("path")));
     }
+
+    // --- web_fetch against a local server ---
+
+    /// Serve `respond(request)` on a local port and return the base URL.
+    async fn serve(respond: impl Fn(&str) -> String + Send + Sync + 'static) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let respond = std::sync::Arc::new(respond);
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let respond = respond.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 8192];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase();
+                    let _ = socket.write_all(respond(&request).as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn http(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+        let mut response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            status,
+            body.len()
+        );
+        for (name, value) in headers {
+            response.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        format!("{}\r\n{}", response, body)
+    }
+
+    fn fetch_config() -> WebFetchConfig {
+        WebFetchConfig {
+            min_host_interval_ms: 0,
+            ..Default::default()
+        }
+    }
+
+    const ARTICLE_HTML: &str = r#"<html><head><title>Release Notes</title>
+<meta name="author" content="Jane Doe"><meta property="article:published_time" content="2026-05-04">
+</head><body><nav><a href="/">Home</a><a href="/blog">Blog</a></nav>
+<article><p>Version 2.0 ships today with a new scheduler, faster startup, and a
+redesigned plugin API that third-party authors have been asking for.</p>
+<p>Upgrading is a drop-in change for most users, though plugins need a rebuild.</p></article>
+<footer>All rights reserved.</footer></body></html>"#;
+
+    #[tokio::test]
+    async fn test_web_fetch_readability_and_raw() {
+        let base = serve(|request| {
+            if request.starts_with("get /robots.txt") {
+                http("404 Not Found", &[], "")
+            } else {
+                http(
+                    "200 OK",
+                    &[("Content-Type", "text/html; charset=utf-8")],
+                    ARTICLE_HTML,
+                )
+            }
+        })
+        .await;
+        let tool = WebFetchTool::new().with_config(fetch_config());
+        let url = format!("{}/notes", base);
+
+        let output = tool.execute(serde_json::json!({"url": url})).await.unwrap();
+        let (header, body) = output.content.split_once("\n\n").unwrap();
+        assert_eq!(
+            header,
+            format!(
+                "Content from {}:\nTitle: Release Notes\nByline: Jane Doe\n\
+                 Published: 2026-05-04\nExtraction: readability (main content)",
+                url
+            )
+        );
+        assert!(body.starts_with("Version 2.0 ships today"));
+        assert!(body.contains("Upgrading is a drop-in change"));
+        assert!(!body.contains("Home") && !body.contains("All rights reserved"));
+
+        let output = tool
+            .execute(serde_json::json!({"url": url, "raw": true, "max_length": 40}))
+            .await
+            .unwrap();
+        assert!(
+            output
+                .content
+                .contains("Extraction: raw (original response body)")
+        );
+        assert!(
+            output
+                .content
+                .contains("<html><head><title>Release Notes</title>")
+        );
+        assert!(output.content.contains("[Truncated at 40 characters."));
+    }
+
+    #[tokio::test]
+    async fn test_web_fetch_respects_robots_unless_ignored() {
+        let base = serve(|request| {
+            if request.starts_with("get /robots.txt") {
+                http("200 OK", &[], "User-agent: *\nDisallow: /private\n")
+            } else {
+                http("200 OK", &[("Content-Type", "text/plain")], "secret notes")
+            }
+        })
+        .await;
+        let tool = WebFetchTool::new().with_config(fetch_config());
+        let url = format!("{}/private/notes.txt", base);
+
+        let output = tool.execute(serde_json::json!({"url": url})).await.unwrap();
+        assert!(output.content.starts_with("robots.txt for "));
+        assert!(output.content.contains("disallows fetching"));
+        assert!(!output.content.contains("secret notes"));
+
+        let output = tool
+            .execute(serde_json::json!({"url": url, "ignore_robots": true}))
+            .await
+            .unwrap();
+        assert!(output.content.contains("Extraction: plain text"));
+        assert!(output.content.contains("Robots: ignored (ignore_robots)"));
+        assert!(output.content.ends_with("\n\nsecret notes"));
+
+        let allowed = format!("{}/public.txt", base);
+        let output = tool
+            .execute(serde_json::json!({"url": allowed}))
+            .await
+            .unwrap();
+        assert!(output.content.ends_with("\n\nsecret notes"));
+    }
+
+    #[tokio::test]
+    async fn test_web_fetch_refuses_binary_and_oversized_responses() {
+        let base = serve(|request| {
+            if request.starts_with("get /robots.txt") {
+                http("404 Not Found", &[], "")
+            } else if request.starts_with("get /video") {
+                http("200 OK", &[("Content-Type", "video/mp4")], "....")
+            } else if request.starts_with("get /streamed") {
+                // No Content-Length: the limit is enforced while streaming.
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\n{}",
+                    "x".repeat(4096)
+                )
+            } else {
+                http(
+                    "200 OK",
+                    &[("Content-Type", "text/plain")],
+                    &"y".repeat(4096),
+                )
+            }
+        })
+        .await;
+        let tool = WebFetchTool::new().with_config(WebFetchConfig {
+            max_response_bytes: 1024,
+            ..fetch_config()
+        });
+
+        let output = tool
+            .execute(serde_json::json!({"url": format!("{}/video", base)}))
+            .await
+            .unwrap();
+        assert!(
+            output
+                .content
+                .contains("content type 'video/mp4' is not a text format")
+        );
+
+        let output = tool
+            .execute(serde_json::json!({"url": format!("{}/big", base)}))
+            .await
+            .unwrap();
+        assert!(
+            output
+                .content
+                .contains("response is 4096 bytes, over the 1024-byte limit")
+        );
+
+        let output = tool
+            .execute(serde_json::json!({"url": format!("{}/streamed", base)}))
+            .await
+            .unwrap();
+        assert!(
+            output
+                .content
+                .contains("response exceeded the 1024-byte limit")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_web_fetch_revalidates_cached_response_with_etag() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let not_modified = std::sync::Arc::new(AtomicUsize::new(0));
+        let counter = not_modified.clone();
+        let base = serve(move |request| {
+            if request.starts_with("get /robots.txt") {
+                http("404 Not Found", &[], "")
+            } else if request.contains("if-none-match: \"v1\"") {
+                counter.fetch_add(1, Ordering::SeqCst);
+                "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".into()
+            } else {
+                http(
+                    "200 OK",
+                    &[("Content-Type", "text/plain"), ("ETag", "\"v1\"")],
+                    "cached body",
+                )
+            }
+        })
+        .await;
+        let tool = WebFetchTool::new().with_config(fetch_config());
+        let url = format!("{}/doc.txt", base);
+
+        let first = tool.execute(serde_json::json!({"url": url})).await.unwrap();
+        assert!(!first.content.contains("Cache:"));
+        let second = tool.execute(serde_json::json!({"url": url})).await.unwrap();
+        assert_eq!(not_modified.load(Ordering::SeqCst), 1);
+        assert!(
+            second
+                .content
+                .contains("Cache: revalidated (304 Not Modified)")
+        );
+        assert!(second.content.ends_with("\n\ncached body"));
+    }
+
+    #[tokio::test]
+    async fn test_web_fetch_js_render_fallback_uses_browser_tab() {
+        use rustant_core::browser::{BrowserSecurityGuard, MockCdpClient};
+        let base = serve(|request| {
+            if request.starts_with("get /robots.txt") {
+                http("404 Not Found", &[], "")
+            } else {
+                http(
+                    "200 OK",
+                    &[("Content-Type", "text/html")],
+                    "<html><head><title>App</title></head><body><div id=\"root\"></div></body></html>",
+                )
+            }
+        })
+        .await;
+        let url = format!("{}/app", base);
+
+        let static_only = WebFetchTool::new().with_config(fetch_config());
+        let output = static_only
+            .execute(serde_json::json!({"url": url}))
+            .await
+            .unwrap();
+        assert!(
+            output
+                .content
+                .contains("Extraction: full page (no main content found)")
+        );
+        assert!(output.content.ends_with("(no readable content)"));
+
+        let mock = std::sync::Arc::new(MockCdpClient::new());
+        mock.set_html(ARTICLE_HTML);
+        let browser = BrowserToolContext::new(
+            mock.clone(),
+            std::sync::Arc::new(BrowserSecurityGuard::new(vec![], vec![])),
+        );
+        let rendering = WebFetchTool::new()
+            .with_config(WebFetchConfig {
+                js_render: true,
+                ..fetch_config()
+            })
+            .with_browser(browser);
+        let output = rendering
+            .execute(serde_json::json!({"url": url}))
+            .await
+            .unwrap();
+        assert!(
+            output
+                .content
+                .contains("Extraction: readability (browser-rendered)")
+        );
+        assert!(output.content.contains("Title: Release Notes"));
+        assert!(output.content.contains("Version 2.0 ships today"));
+
+        let calls = mock.call_log.lock().unwrap().clone();
+        let methods: Vec<&str> = calls.iter().map(|(m, _)| m.as_str()).collect();
+        assert!(calls.contains(&("new_tab".to_string(), vec![format!("{}/app", base)])));
+        assert!(methods.contains(&"close_tab"));
+        assert_eq!(methods.last(), Some(&"switch_tab"));
+        assert_eq!(mock.active_tab.lock().unwrap().as_str(), "tab-0");
+    }
 }
@@ -0,0 +1,1110 @@
+//! Readability-style main-content extraction for `web_fetch`.
+//!
+//! Parses HTML into a small arena DOM with a forgiving tokenizer, scores
+//! block containers by paragraph text, comma count, class/id hints and link
+//! density, and renders the best candidate (plus related siblings) as
+//! markdown. Title, byline, publish date and site name come from `<meta>`
+//! tags, JSON-LD and common byline markup.
+
+use reqwest::Url;
+use std::collections::HashMap;
+
+/// Main content and metadata extracted from an HTML page.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Article {
+    pub title: Option<String>,
+    pub byline: Option<String>,
+    pub published: Option<String>,
+    pub site_name: Option<String>,
+    /// Page content rendered as markdown.
+    pub markdown: String,
+    /// Whether a main-content block was found. When `false`, `markdown` is
+    /// the whole `<body>`.
+    pub main_content: bool,
+}
+
+/// Extract the main content of `html` as markdown. Relative links and
+/// images are resolved against `base`.
+pub fn extract_article(html: &str, base: Option<&Url>) -> Article {
+    let mut dom = Dom::parse(html);
+    let mut article = Article {
+        title: dom.title(),
+        byline: dom.byline(),
+        published: dom.published(),
+        site_name: dom.meta(&["og:site_name", "application-name"]),
+        ..Default::default()
+    };
+
+    let body = dom.find_first(0, "body").unwrap_or(0);
+    dom.strip_unlikely(body);
+    let mut renderer = Renderer::new(&dom, base);
+    match dom.best_candidate(body) {
+        Some(top) => {
+            for id in dom.with_related_siblings(top) {
+                renderer.node(id);
+            }
+            article.main_content = true;
+        }
+        None => renderer.node(body),
+    }
+    article.markdown = renderer.finish();
+
+    // Drop a leading heading that just repeats the title.
+    if let Some(title) = &article.title
+        && let Some(first) = article.markdown.lines().next()
+        && first.trim_start_matches('#').trim() == title.as_str()
+    {
+        article.markdown = article.markdown[first.len()..].trim_start().to_string();
+    }
+    article
+}
+
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+const RAW_TEXT_TAGS: &[&str] = &[
+    "script", "style", "title", "textarea", "noscript", "template",
+];
+/// Elements never rendered as content.
+const SKIPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "head", "button", "form", "input", "select",
+    "textarea", "svg", "canvas", "iframe", "object", "embed",
+];
+/// Elements removed before scoring: navigation and page chrome.
+const CHROME_TAGS: &[&str] = &["nav", "aside", "footer", "dialog", "menu"];
+/// Elements whose opening implicitly closes an open `<p>`.
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "div",
+    "dl",
+    "fieldset",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+const UNLIKELY_HINTS: &[&str] = &[
+    "advert",
+    "banner",
+    "breadcrumb",
+    "comment",
+    "cookie",
+    "disqus",
+    "footer",
+    "menu",
+    "modal",
+    "newsletter",
+    "pagination",
+    "popup",
+    "promo",
+    "related",
+    "share",
+    "sidebar",
+    "social",
+    "sponsor",
+    "subscribe",
+    "widget",
+];
+const POSITIVE_HINTS: &[&str] = &[
+    "article", "body", "content", "entry", "main", "page", "post", "story", "text",
+];
+const NEGATIVE_HINTS: &[&str] = &[
+    "comment", "footer", "masthead", "meta", "nav", "promo", "related", "share", "sidebar",
+    "sponsor", "widget",
+];
+
+#[derive(Debug)]
+enum NodeKind {
+    Document,
+    Element {
+        tag: String,
+        attrs: Vec<(String, String)>,
+    },
+    Text(String),
+}
+
+#[derive(Debug)]
+struct Node {
+    parent: Option<usize>,
+    children: Vec<usize>,
+    kind: NodeKind,
+}
+
+/// Arena DOM; node 0 is the document.
+struct Dom {
+    nodes: Vec<Node>,
+}
+
+impl Dom {
+    fn parse(html: &str) -> Self {
+        let mut dom = Dom {
+            nodes: vec![Node {
+                parent: None,
+                children: Vec::new(),
+                kind: NodeKind::Document,
+            }],
+        };
+        let mut stack: Vec<usize> = vec![0];
+        let bytes = html.as_bytes();
+        let mut i = 0;
+        let mut text_start = 0;
+
+        while i < bytes.len() {
+            if bytes[i] != b'<' {
+                i += 1;
+                continue;
+            }
+            let next = bytes.get(i + 1).copied().unwrap_or(b' ');
+            let is_markup = next.is_ascii_alphabetic() || matches!(next, b'/' | b'!' | b'?');
+            if !is_markup {
+                i += 1;
+                continue;
+            }
+            dom.push_text(&stack, &html[text_start..i]);
+
+            if html[i..].starts_with("<!--") {
+                i = find_from(html, i + 4, "-->").map_or(bytes.len(), |end| end + 3);
+            } else if matches!(next, b'!' | b'?') {
+                i = find_from(html, i, ">").map_or(bytes.len(), |end| end + 1);
+            } else if next == b'/' {
+                let end = find_from(html, i, ">").unwrap_or(bytes.len());
+                let name = tag_name(&html[i + 2..end]);
+                i = (end + 1).min(bytes.len());
+                if let Some(pos) = stack.iter().rposition(|&id| dom.tag(id) == Some(&name)) {
+                    stack.truncate(pos.max(1));
+                }
+            } else {
+                let end = tag_end(html, i + 1);
+                let inner = &html[i + 1..end];
+                i = (end + 1).min(bytes.len());
+                let self_closing = inner.trim_end().ends_with('/');
+                let name = tag_name(inner);
+                let attrs = parse_attrs(&inner[name.len().min(inner.len())..]);
+                dom.close_implied(&mut stack, &name);
+                let id = dom.append(
+                    *stack.last().unwrap_or(&0),
+                    NodeKind::Element {
+                        tag: name.clone(),
+                        attrs,
+                    },
+                );
+                if RAW_TEXT_TAGS.contains(&name.as_str()) {
+                    let close = format!("</{}", name);
+                    let content_end = find_ci(html, i, &close).unwrap_or(bytes.len());
+                    let content = &html[i..content_end];
+                    if !content.is_empty() {
+                        let text = if name == "title" || name == "textarea" {
+                            decode_entities(content)
+                        } else {
+                            content.to_string()
+                        };
+                        dom.append(id, NodeKind::Text(text));
+                    }
+                    i = find_from(html, content_end, ">").map_or(bytes.len(), |e| e + 1);
+                } else if !self_closing && !VOID_TAGS.contains(&name.as_str()) {
+                    stack.push(id);
+                }
+            }
+            text_start = i;
+        }
+        dom.push_text(&stack, &html[text_start.min(bytes.len())..]);
+        dom
+    }
+
+    fn append(&mut self, parent: usize, kind: NodeKind) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            parent: Some(parent),
+            children: Vec::new(),
+            kind,
+        });
+        self.nodes[parent].children.push(id);
+        id
+    }
+
+    fn push_text(&mut self, stack: &[usize], raw: &str) {
+        if !raw.is_empty() {
+            let parent = *stack.last().unwrap_or(&0);
+            self.append(parent, NodeKind::Text(decode_entities(raw)));
+        }
+    }
+
+    /// Pop elements that the opening of `tag` closes implicitly.
+    fn close_implied(&self, stack: &mut Vec<usize>, tag: &str) {
+        let (closes, scope): (&[&str], &[&str]) = match tag {
+            "li" => (&["li"], &["ul", "ol", "menu"]),
+            "dt" | "dd" => (&["dt", "dd"], &["dl"]),
+            "tr" => (&["tr", "td", "th"], &["table", "tbody", "thead", "tfoot"]),
+            "td" | "th" => (&["td", "th"], &["tr", "table"]),
+            "option" => (&["option"], &["select", "datalist"]),
+            t if BLOCK_TAGS.contains(&t) => (&["p"], &["div", "section", "article", "td", "li"]),
+            _ => return,
+        };
+        for pos in (1..stack.len()).rev() {
+            let Some(open) = self.tag(stack[pos]) else {
+                break;
+            };
+            if closes.contains(&open.as_str()) {
+                stack.truncate(pos);
+                return;
+            }
+            if scope.contains(&open.as_str()) {
+                return;
+            }
+        }
+    }
+
+    fn tag(&self, id: usize) -> Option<&String> {
+        match &self.nodes[id].kind {
+            NodeKind::Element { tag, .. } => Some(tag),
+            _ => None,
+        }
+    }
+
+    fn attr(&self, id: usize, name: &str) -> Option<&str> {
+        match &self.nodes[id].kind {
+            NodeKind::Element { attrs, .. } => attrs
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str()),
+            _ => None,
+        }
+    }
+
+    fn descendants(&self, root: usize) -> Vec<usize> {
+        let mut out = Vec::new();
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            out.push(id);
+            stack.extend(self.nodes[id].children.iter().rev());
+        }
+        out
+    }
+
+    fn find_first(&self, root: usize, tag: &str) -> Option<usize> {
+        self.descendants(root)
+            .into_iter()
+            .find(|&id| self.tag(id).is_some_and(|t| t == tag))
+    }
+
+    /// Visible text of a subtree, whitespace-collapsed.
+    fn text(&self, id: usize) -> String {
+        let mut raw = String::new();
+        self.collect_text(id, &mut raw);
+        collapse_whitespace(&raw)
+    }
+
+    fn collect_text(&self, id: usize, out: &mut String) {
+        match &self.nodes[id].kind {
+            NodeKind::Text(t) => out.push_str(t),
+            NodeKind::Element { tag, .. } if SKIPPED_TAGS.contains(&tag.as_str()) => {}
+            _ => {
+                for &child in &self.nodes[id].children {
+                    self.collect_text(child, out);
+                    out.push(' ');
+                }
+            }
+        }
+    }
+
+    fn class_and_id(&self, id: usize) -> String {
+        format!(
+            "{} {}",
+            self.attr(id, "class").unwrap_or(""),
+            self.attr(id, "id").unwrap_or("")
+        )
+        .to_ascii_lowercase()
+    }
+
+    fn meta(&self, keys: &[&str]) -> Option<String> {
+        for key in keys {
+            for id in self.descendants(0) {
+                if self.tag(id).is_some_and(|t| t == "meta") {
+                    let name = self
+                        .attr(id, "property")
+                        .or_else(|| self.attr(id, "name"))
+                        .or_else(|| self.attr(id, "itemprop"))
+                        .unwrap_or("");
+                    if name.eq_ignore_ascii_case(key)
+                        && let Some(content) = self.attr(id, "content")
+                        && !content.trim().is_empty()
+                    {
+                        return Some(collapse_whitespace(content));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// JSON-LD objects from `<script type="application/ld+json">`, with
+    /// `@graph` arrays flattened.
+    fn json_ld(&self) -> Vec<serde_json::Value> {
+        let mut out = Vec::new();
+        for id in self.descendants(0) {
+            if self.tag(id).is_some_and(|t| t == "script")
+                && self
+                    .attr(id, "type")
+                    .is_some_and(|t| t.eq_ignore_ascii_case("application/ld+json"))
+            {
+                let mut raw = String::new();
+                for &child in &self.nodes[id].children {
+                    if let NodeKind::Text(t) = &self.nodes[child].kind {
+                        raw.push_str(t);
+                    }
+                }
+                let Ok(value) = serde_json::from_str::<serde_json::Value>(raw.trim()) else {
+                    continue;
+                };
+                let mut pending = vec![value];
+                while let Some(value) = pending.pop() {
+                    match value {
+                        serde_json::Value::Array(items) => pending.extend(items),
+                        serde_json::Value::Object(mut map) => {
+                            if let Some(graph) = map.remove("@graph") {
+                                pending.push(graph);
+                            }
+                            out.push(serde_json::Value::Object(map));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn json_ld_field(&self, field: &str) -> Option<String> {
+        self.json_ld().iter().find_map(|obj| {
+            let value = obj.get(field)?;
+            let value = match value {
+                serde_json::Value::Array(items) => items.first()?,
+                other => other,
+            };
+            match value {
+                serde_json::Value::String(s) => Some(s.trim().to_string()),
+                serde_json::Value::Object(o) => o
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .map(|s| s.trim().to_string()),
+                _ => None,
+            }
+            .filter(|s| !s.is_empty())
+        })
+    }
+
+    fn title(&self) -> Option<String> {
+        self.meta(&["og:title", "twitter:title"])
+            .or_else(|| self.json_ld_field("headline"))
+            .or_else(|| {
+                self.find_first(0, "title")
+                    .map(|id| self.text(id))
+                    .filter(|t| !t.is_empty())
+            })
+            .or_else(|| {
+                self.find_first(0, "h1")
+                    .map(|id| self.text(id))
+                    .filter(|t| !t.is_empty())
+            })
+    }
+
+    fn byline(&self) -> Option<String> {
+        let meta = self
+            .meta(&["author", "article:author", "twitter:creator", "dc.creator"])
+            .filter(|a| !a.starts_with("http"));
+        meta.or_else(|| self.json_ld_field("author")).or_else(|| {
+            self.descendants(0).into_iter().find_map(|id| {
+                self.tag(id)?;
+                let hinted = self.attr(id, "rel") == Some("author")
+                    || self.attr(id, "itemprop") == Some("author")
+                    || {
+                        let hints = self.class_and_id(id);
+                        hints.contains("byline") || hints.contains("author")
+                    };
+                let text = self.text(id);
+                (hinted && !text.is_empty() && text.chars().count() < 100).then_some(text)
+            })
+        })
+    }
+
+    fn published(&self) -> Option<String> {
+        self.meta(&[
+            "article:published_time",
+            "datePublished",
+            "date",
+            "pubdate",
+            "publish-date",
+            "dc.date",
+            "dc.date.issued",
+        ])
+        .or_else(|| self.json_ld_field("datePublished"))
+        .or_else(|| {
+            self.descendants(0).into_iter().find_map(|id| {
+                (self.tag(id)? == "time")
+                    .then(|| self.attr(id, "datetime").map(str::to_string))
+                    .flatten()
+            })
+        })
+    }
+
+    fn detach(&mut self, id: usize) {
+        if let Some(parent) = self.nodes[id].parent.take() {
+            self.nodes[parent].children.retain(|&c| c != id);
+        }
+    }
+
+    /// Remove page chrome and elements whose class/id marks them as
+    /// unlikely to be content.
+    fn strip_unlikely(&mut self, root: usize) {
+        let doomed: Vec<usize> = self
+            .descendants(root)
+            .into_iter()
+            .filter(|&id| id != root)
+            .filter(|&id| {
+                let Some(tag) = self.tag(id) else {
+                    return false;
+                };
+                if CHROME_TAGS.contains(&tag.as_str()) || SKIPPED_TAGS.contains(&tag.as_str()) {
+                    return true;
+                }
+                if matches!(tag.as_str(), "body" | "article" | "main" | "a") {
+                    return false;
+                }
+                let hints = self.class_and_id(id);
+                self.attr(id, "role")
+                    .is_some_and(|r| matches!(r, "navigation" | "complementary" | "dialog"))
+                    || (UNLIKELY_HINTS.iter().any(|h| hints.contains(h))
+                        && !POSITIVE_HINTS.iter().any(|h| hints.contains(h)))
+            })
+            .collect();
+        for id in doomed {
+            self.detach(id);
+        }
+    }
+
+    fn class_weight(&self, id: usize) -> f64 {
+        let hints = self.class_and_id(id);
+        let mut weight = 0.0;
+        if POSITIVE_HINTS.iter().any(|h| hints.contains(h)) {
+            weight += 25.0;
+        }
+        if NEGATIVE_HINTS.iter().any(|h| hints.contains(h)) {
+            weight -= 25.0;
+        }
+        weight
+    }
+
+    fn link_density(&self, id: usize) -> f64 {
+        let total = self.text(id).chars().count();
+        if total == 0 {
+            return 0.0;
+        }
+        let linked: usize = self
+            .descendants(id)
+            .into_iter()
+            .filter(|&d| self.tag(d).is_some_and(|t| t == "a"))
+            .map(|d| self.text(d).chars().count())
+            .sum();
+        (linked as f64 / total as f64).min(1.0)
+    }
+
+    /// Highest-scoring container below `root`, if any paragraph-like text
+    /// was found.
+    fn best_candidate(&self, root: usize) -> Option<usize> {
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        for id in self.descendants(root) {
+            let Some(tag) = self.tag(id) else { continue };
+            let scorable = matches!(tag.as_str(), "p" | "pre" | "td" | "blockquote" | "li")
+                || (tag == "div"
+                    && !self.nodes[id].children.iter().any(|&c| {
+                        self.tag(c)
+                            .is_some_and(|t| BLOCK_TAGS.contains(&t.as_str()) || t == "li")
+                    }));
+            if !scorable {
+                continue;
+            }
+            let text = self.text(id);
+            let len = text.chars().count();
+            if len < 25 {
+                continue;
+            }
+            let score = 1.0 + text.matches(',').count() as f64 + (len as f64 / 100.0).min(3.0);
+            let mut ancestor = self.nodes[id].parent;
+            for level in 0..3 {
+                let Some(a) = ancestor else { break };
+                if a == 0 {
+                    break;
+                }
+                let entry = scores.entry(a).or_insert_with(|| self.initial_score(a));
+                *entry += score / [1.0, 2.0, 3.0][level];
+                ancestor = self.nodes[a].parent;
+            }
+        }
+        scores
+            .into_iter()
+            .map(|(id, score)| (id, score * (1.0 - self.link_density(id))))
+            .filter(|(_, score)| *score > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+            .map(|(id, _)| id)
+    }
+
+    fn initial_score(&self, id: usize) -> f64 {
+        let base = match self.tag(id).map(String::as_str) {
+            Some("article" | "main") => 10.0,
+            Some("div") => 5.0,
+            Some("pre" | "td" | "blockquote") => 3.0,
+            Some("ol" | "ul" | "dl" | "form") => -3.0,
+            Some("h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th") => -5.0,
+            _ => 0.0,
+        };
+        base + self.class_weight(id)
+    }
+
+    /// `top` plus siblings that look like part of the same content: long,
+    /// link-poor paragraphs or blocks sharing its class.
+    fn with_related_siblings(&self, top: usize) -> Vec<usize> {
+        let Some(parent) = self.nodes[top].parent else {
+            return vec![top];
+        };
+        let top_class = self.attr(top, "class").unwrap_or("");
+        self.nodes[parent]
+            .children
+            .iter()
+            .copied()
+            .filter(|&id| {
+                if id == top {
+                    return true;
+                }
+                let Some(tag) = self.tag(id) else {
+                    return false;
+                };
+                let len = self.text(id).chars().count();
+                let same_class = !top_class.is_empty() && self.attr(id, "class") == Some(top_class);
+                (same_class && len > 80) || (tag == "p" && len > 80 && self.link_density(id) < 0.25)
+            })
+            .collect()
+    }
+}
+
+/// Markdown writer over a [`Dom`].
+struct Renderer<'a> {
+    dom: &'a Dom,
+    base: Option<&'a Url>,
+    out: String,
+    lists: Vec<Option<usize>>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(dom: &'a Dom, base: Option<&'a Url>) -> Self {
+        Self {
+            dom,
+            base,
+            out: String::new(),
+            lists: Vec::new(),
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut lines: Vec<&str> = self.out.lines().map(str::trim_end).collect();
+        lines.dedup_by(|a, b| a.is_empty() && b.is_empty());
+        lines.join("\n").trim().to_string()
+    }
+
+    fn block_break(&mut self) {
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push_str(if self.out.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            });
+        }
+    }
+
+    fn line_break(&mut self) {
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let collapsed = collapse_inline(text);
+        let at_start = self.out.is_empty() || self.out.ends_with([' ', '\n']);
+        let collapsed = if at_start {
+            collapsed.trim_start()
+        } else {
+            &collapsed
+        };
+        self.out.push_str(collapsed);
+    }
+
+    /// Render children into a separate buffer and return it trimmed.
+    fn inline(&mut self, id: usize) -> String {
+        let saved = std::mem::take(&mut self.out);
+        self.children(id);
+        let inner = std::mem::replace(&mut self.out, saved);
+        inner.trim().to_string()
+    }
+
+    fn children(&mut self, id: usize) {
+        for &child in &self.dom.nodes[id].children {
+            self.node(child);
+        }
+    }
+
+    fn resolve(&self, href: &str) -> Option<String> {
+        let href = href.trim();
+        if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+            return None;
+        }
+        match self.base {
+            Some(base) => base.join(href).ok().map(|u| u.to_string()),
+            None => Some(href.to_string()),
+        }
+    }
+
+    fn node(&mut self, id: usize) {
+        let tag = match &self.dom.nodes[id].kind {
+            NodeKind::Document => return self.children(id),
+            NodeKind::Text(t) => return self.push_text(t),
+            NodeKind::Element { tag, .. } => tag.as_str(),
+        };
+        match tag {
+            t if SKIPPED_TAGS.contains(&t) || t == "title" => {}
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = self.inline(id);
+                if !text.is_empty() {
+                    let level = tag[1..].parse::<usize>().unwrap_or(1);
+                    self.block_break();
+                    self.out.push_str(&"#".repeat(level));
+                    self.out.push(' ');
+                    self.out.push_str(&text.replace('\n', " "));
+                    self.block_break();
+                }
+            }
+            "br" => self.out.push('\n'),
+            "hr" => {
+                self.block_break();
+                self.out.push_str("---");
+                self.block_break();
+            }
+            "pre" => {
+                let mut code = String::new();
+                self.dom.collect_raw(id, &mut code);
+                let code = code.trim_matches('\n');
+                if !code.trim().is_empty() {
+                    self.block_break();
+                    self.out.push_str("```\n");
+                    self.out.push_str(code);
+                    self.out.push_str("\n```");
+                    self.block_break();
+                }
+            }
+            "code" | "kbd" | "samp" => {
+                let text = self.dom.text(id);
+                if !text.is_empty() {
+                    self.push_inline(&format!("`{}`", text));
+                }
+            }
+            "strong" | "b" => self.wrap(id, "**"),
+            "em" | "i" => self.wrap(id, "_"),
+            "a" => {
+                let text = self.inline(id);
+                if text.is_empty() {
+                    return;
+                }
+                match self.dom.attr(id, "href").and_then(|h| self.resolve(h)) {
+                    Some(href) => self.push_inline(&format!("[{}]({})", text, href)),
+                    None => self.push_inline(&text),
+                }
+            }
+            "img" => {
+                let alt = collapse_whitespace(self.dom.attr(id, "alt").unwrap_or(""));
+                if let Some(src) = self.dom.attr(id, "src").and_then(|s| self.resolve(s))
+                    && !alt.is_empty()
+                {
+                    self.push_inline(&format!("![{}]({})", alt, src));
+                }
+            }
+            "ul" | "ol" => {
+                self.block_break();
+                self.lists.push((tag == "ol").then_some(0));
+                self.children(id);
+                self.lists.pop();
+                self.block_break();
+            }
+            "li" => {
+                self.line_break();
+                let depth = self.lists.len().max(1) - 1;
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}. ", n)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.out.push_str(&"  ".repeat(depth));
+                self.out.push_str(&marker);
+                self.children(id);
+                self.line_break();
+            }
+            "blockquote" => {
+                let inner = self.inline(id);
+                if !inner.is_empty() {
+                    self.block_break();
+                    let quoted: Vec<String> = inner
+                        .lines()
+                        .map(|l| format!("> {}", l).trim_end().to_string())
+                        .collect();
+                    self.out.push_str(&quoted.join("\n"));
+                    self.block_break();
+                }
+            }
+            "table" => self.table(id),
+            "p" | "div" | "section" | "article" | "main" | "header" | "figure" | "figcaption"
+            | "dl" | "dt" | "dd" | "address" | "details" | "summary" => {
+                self.block_break();
+                self.children(id);
+                self.block_break();
+            }
+            _ => self.children(id),
+        }
+    }
+
+    fn push_inline(&mut self, text: &str) {
+        if !self.out.is_empty() && !self.out.ends_with([' ', '\n', '(', '[']) {
+            let last = self.out.chars().last().unwrap_or(' ');
+            if last.is_alphanumeric() || matches!(last, '.' | ',' | ':' | ';' | '!' | '?') {
+                self.out.push(' ');
+            }
+        }
+        self.out.push_str(text);
+    }
+
+    fn wrap(&mut self, id: usize, marker: &str) {
+        let inner = self.inline(id);
+        if !inner.is_empty() {
+            self.push_inline(&format!("{marker}{inner}{marker}"));
+        }
+    }
+
+    fn table(&mut self, id: usize) {
+        let rows: Vec<Vec<String>> = self
+            .dom
+            .descendants(id)
+            .into_iter()
+            .filter(|&r| self.dom.tag(r).is_some_and(|t| t == "tr"))
+            .map(|r| {
+                self.dom.nodes[r]
+                    .children
+                    .iter()
+                    .filter(|&&c| self.dom.tag(c).is_some_and(|t| t == "td" || t == "th"))
+                    .map(|&c| self.dom.text(c).replace('|', "\\|"))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells| !cells.is_empty())
+            .collect();
+        if rows.is_empty() {
+            return;
+        }
+        self.block_break();
+        for (i, cells) in rows.iter().enumerate() {
+            self.out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            if i == 0 {
+                self.out
+                    .push_str(&format!("|{}\n", " --- |".repeat(cells.len())));
+            }
+        }
+        self.block_break();
+    }
+}
+
+impl Dom {
+    /// Text of a subtree with whitespace preserved, for `<pre>` blocks.
+    fn collect_raw(&self, id: usize, out: &mut String) {
+        match &self.nodes[id].kind {
+            NodeKind::Text(t) => out.push_str(t),
+            NodeKind::Element { tag, .. } if tag == "br" => out.push('\n'),
+            _ => {
+                for &child in &self.nodes[id].children {
+                    self.collect_raw(child, out);
+                }
+            }
+        }
+    }
+}
+
+fn find_from(haystack: &str, from: usize, needle: &str) -> Option<usize> {
+    haystack
+        .get(from..)
+        .and_then(|rest| rest.find(needle))
+        .map(|pos| from + pos)
+}
+
+/// Case-insensitive search, for closing raw-text tags like `</SCRIPT`.
+fn find_ci(haystack: &str, from: usize, needle: &str) -> Option<usize> {
+    let hay = haystack.as_bytes();
+    let needle = needle.as_bytes();
+    let last = hay.len().checked_sub(needle.len())?;
+    (from..=last).find(|&i| hay[i..i + needle.len()].eq_ignore_ascii_case(needle))
+}
+
+/// End of a start tag (the index of its `>`), skipping over quoted values.
+fn tag_end(html: &str, from: usize) -> usize {
+    let mut quote = None;
+    for (offset, b) in html.as_bytes()[from..].iter().enumerate() {
+        match (quote, b) {
+            (Some(q), _) if *b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(*b),
+            (None, b'>') => return from + offset,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+fn tag_name(inner: &str) -> String {
+    inner
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == ':')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+fn parse_attrs(s: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut chars = s.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace() || *c == '/') {
+            chars.next();
+        }
+        let name: String = std::iter::from_fn(|| {
+            chars.next_if(|c| !c.is_whitespace() && !matches!(c, '=' | '>' | '/'))
+        })
+        .collect();
+        if name.is_empty() {
+            break;
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            match chars.peek().copied() {
+                Some(q @ ('"' | '\'')) => {
+                    chars.next();
+                    value = std::iter::from_fn(|| chars.next_if(|c| *c != q)).collect();
+                    chars.next();
+                }
+                _ => {
+                    value = std::iter::from_fn(|| chars.next_if(|c| !c.is_whitespace())).collect();
+                }
+            }
+        }
+        attrs.push((name.to_ascii_lowercase(), decode_entities(&value)));
+    }
+    attrs
+}
+
+/// Decode named and numeric character references.
+fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let end = rest[1..]
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '#')
+            .map_or(rest.len(), |e| e + 1);
+        let entity = &rest[1..end];
+        let decoded = if let Some(num) = entity.strip_prefix('#') {
+            let code = match num.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => num.parse::<u32>().ok(),
+            };
+            code.and_then(char::from_u32).map(String::from)
+        } else {
+            named_entity(entity).map(String::from)
+        };
+        match decoded {
+            Some(text) => {
+                out.push_str(&text);
+                rest = rest[end..].strip_prefix(';').unwrap_or(&rest[end..]);
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn named_entity(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "amp" => "&",
+        "lt" => "<",
+        "gt" => ">",
+        "quot" => "\"",
+        "apos" => "'",
+        "nbsp" => " ",
+        "mdash" => "—",
+        "ndash" => "–",
+        "hellip" => "…",
+        "lsquo" => "‘",
+        "rsquo" => "’",
+        "ldquo" => "“",
+        "rdquo" => "”",
+        "laquo" => "«",
+        "raquo" => "»",
+        "bull" => "•",
+        "middot" => "·",
+        "copy" => "©",
+        "reg" => "®",
+        "trade" => "™",
+        "deg" => "°",
+        "times" => "×",
+        "euro" => "€",
+        "pound" => "£",
+        _ => return None,
+    })
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Collapse whitespace runs to one space, keeping a leading or trailing
+/// space so adjacent inline nodes stay separated.
+fn collapse_inline(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_space = false;
+    for c in s.chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                out.push(' ');
+            }
+            in_space = true;
+        } else {
+            out.push(c);
+            in_space = false;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r#"<!DOCTYPE html>
+<html><head>
+<title>Fallback Title | Example News</title>
+<meta property="og:title" content="Rust 2026 &amp; Beyond">
+<meta property="og:site_name" content="Example News">
+<meta name="author" content="Ada Lovelace">
+<meta property="article:published_time" content="2026-03-01T09:00:00Z">
+<style>.x { color: red }</style>
+<script>var ads = "<p>not content</p>";</script>
+</head><body>
+<nav><a href="/">Home</a> <a href="/world">World</a> <a href="/tech">Tech</a></nav>
+<div class="sidebar"><p>Subscribe to our newsletter, and get updates, offers, news.</p></div>
+<article class="post-content">
+  <h1>Rust 2026 &amp; Beyond</h1>
+  <p>The Rust project published its roadmap today, covering the compiler, the
+  standard library, and the ecosystem at large, with <a href="/roadmap">details here</a>.</p>
+  <h2>Highlights</h2>
+  <ul><li>Faster builds<li>Better <strong>async</strong> support</ul>
+  <p>Contributors said the plan reflects feedback from users, maintainers, and
+  companies, and that work is already under way on several items.</p>
+  <pre><code>fn main() {
+    println!("hi");
+}</code></pre>
+  <p>Read the <em>full</em> announcement for more, including timelines and goals.</p>
+</article>
+<footer>Copyright &copy; 2026, Example News. All rights reserved.</footer>
+</body></html>"#;
+
+    #[test]
+    fn test_extract_article_main_content_and_metadata() {
+        let base = Url::parse("https://news.example.com/2026/rust").unwrap();
+        let article = extract_article(ARTICLE, Some(&base));
+        assert!(article.main_content);
+        assert_eq!(article.title.as_deref(), Some("Rust 2026 & Beyond"));
+        assert_eq!(article.byline.as_deref(), Some("Ada Lovelace"));
+        assert_eq!(article.published.as_deref(), Some("2026-03-01T09:00:00Z"));
+        assert_eq!(article.site_name.as_deref(), Some("Example News"));
+
+        let md = &article.markdown;
+        assert!(md.starts_with("The Rust project published its roadmap today"));
+        assert!(md.contains("[details here](https://news.example.com/roadmap)"));
+        assert!(md.contains("## Highlights\n\n- Faster builds\n- Better **async** support"));
+        assert!(md.contains("```\nfn main() {\n    println!(\"hi\");\n}\n```"));
+        assert!(md.contains("Read the _full_ announcement"));
+        for chrome in [
+            "Home",
+            "newsletter",
+            "Copyright",
+            "not content",
+            "color: red",
+        ] {
+            assert!(!md.contains(chrome), "{chrome} leaked into {md}");
+        }
+    }
+
+    #[test]
+    fn test_extract_article_json_ld_and_fallbacks() {
+        let html = r#"<html><head><title>Plain</title>
+<script type="application/ld+json">{"@context":"https://schema.org","@graph":[
+  {"@type":"NewsArticle","datePublished":"2026-01-02","author":[{"@type":"Person","name":"Grace Hopper"}]}]}
+</script></head><body><div id="root"></div></body></html>"#;
+        let article = extract_article(html, None);
+        assert_eq!(article.title.as_deref(), Some("Plain"));
+        assert_eq!(article.byline.as_deref(), Some("Grace Hopper"));
+        assert_eq!(article.published.as_deref(), Some("2026-01-02"));
+        assert!(!article.main_content);
+        assert!(article.markdown.is_empty());
+    }
+
+    #[test]
+    fn test_parser_tolerates_malformed_markup_and_entities() {
+        let html = "<body><div class=content><p>Caf&eacute; &#233;&#x41; 5 &lt; 6 &unknown; \
+                    <b>bold<i>both</b> tail<p>Second, unclosed paragraph with enough text \
+                    to count<table><tr><th>a<th>b|c<tr><td>1<td>2</table><img src=x.png alt='A pic'></div>";
+        let article = extract_article(html, None);
+        let md = &article.markdown;
+        assert!(md.contains("Caf&eacute; éA 5 < 6 &unknown;"), "{md}");
+        assert!(md.contains("**bold _both_**"), "{md}");
+        assert!(
+            md.contains("| a | b\\|c |\n| --- | --- |\n| 1 | 2 |"),
+            "{md}"
+        );
+        assert!(md.contains("![A pic](x.png)"), "{md}");
+    }
+}
@@ -0,0 +1,352 @@
+//! Per-domain politeness for `web_fetch`: robots.txt rules, request spacing
+//! and a response cache revalidated with ETag / Last-Modified.
+//!
+//! [`FetchState::shared`] is process-wide, so every `web_fetch` instance
+//! (agent executors, the tool registry, tools that fetch internally) shares
+//! the same robots cache, host clock and response cache.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a fetched robots.txt is trusted before it is fetched again.
+pub const ROBOTS_TTL: Duration = Duration::from_secs(3600);
+/// Upper bound on a robots.txt `Crawl-delay`, so one site cannot stall a call.
+pub const MAX_CRAWL_DELAY: Duration = Duration::from_secs(10);
+
+/// The rules from a robots.txt that apply to one user agent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsRules {
+    /// `(allow, pattern)` pairs.
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<Duration>,
+}
+
+impl RobotsRules {
+    /// Rules that allow everything, used when robots.txt is missing (4xx).
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Rules that disallow everything, used when robots.txt is unreachable
+    /// because of a server error (RFC 9309 §2.3.1.4).
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: vec![(false, "/".into())],
+            crawl_delay: None,
+        }
+    }
+
+    /// Parse a robots.txt body, keeping the groups for `user_agent`'s product
+    /// token (the part before `/`), or the `*` groups when none match.
+    pub fn parse(body: &str, user_agent: &str) -> Self {
+        let token = user_agent
+            .split('/')
+            .next()
+            .unwrap_or(user_agent)
+            .trim()
+            .to_ascii_lowercase();
+
+        let mut groups: Vec<RobotsGroup> = Vec::new();
+        let mut in_agent_lines = false;
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+            match key.as_str() {
+                "user-agent" => {
+                    if !in_agent_lines {
+                        groups.push(RobotsGroup::default());
+                    }
+                    in_agent_lines = true;
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                "allow" | "disallow" => {
+                    in_agent_lines = false;
+                    if let Some(group) = groups.last_mut()
+                        && !value.is_empty()
+                    {
+                        group.rules.push((key == "allow", value.to_string()));
+                    }
+                }
+                "crawl-delay" => {
+                    in_agent_lines = false;
+                    if let Some(group) = groups.last_mut() {
+                        group.crawl_delay = value
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+                            .map(|secs| Duration::from_secs_f64(secs).min(MAX_CRAWL_DELAY));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let matching = |wanted: &dyn Fn(&str) -> bool| {
+            groups
+                .iter()
+                .filter(|group| group.agents.iter().any(|a| wanted(a)))
+                .fold(Self::default(), |mut acc, group| {
+                    acc.rules.extend(group.rules.iter().cloned());
+                    acc.crawl_delay = acc.crawl_delay.max(group.crawl_delay);
+                    acc
+                })
+        };
+        let specific = matching(&|agent: &str| !token.is_empty() && agent == token);
+        let has_specific = groups.iter().any(|group| {
+            group
+                .agents
+                .iter()
+                .any(|a| !token.is_empty() && *a == token)
+        });
+        if has_specific {
+            specific
+        } else {
+            matching(&|agent: &str| agent == "*")
+        }
+    }
+
+    /// Whether `path` (path plus query) may be fetched. The longest matching
+    /// pattern wins; `Allow` wins a tie.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+}
+
+/// One `User-agent` group of a robots.txt.
+#[derive(Default)]
+struct RobotsGroup {
+    agents: Vec<String>,
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<Duration>,
+}
+
+/// Match a robots.txt path pattern supporting `*` wildcards and a trailing
+/// `$` end anchor.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i + 1 == parts.len();
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// A response kept for conditional revalidation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedResponse {
+    pub body: String,
+    pub content_type: String,
+    pub final_url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Bounded URL → response cache, evicting the least recently used entry.
+#[derive(Debug, Default)]
+pub struct ResponseCache {
+    entries: HashMap<String, CachedResponse>,
+    order: VecDeque<String>,
+}
+
+impl ResponseCache {
+    pub fn get(&mut self, url: &str) -> Option<CachedResponse> {
+        let entry = self.entries.get(url)?.clone();
+        self.touch(url);
+        Some(entry)
+    }
+
+    /// Store a response if it carries a validator; `capacity` 0 disables
+    /// caching.
+    pub fn insert(&mut self, url: &str, response: CachedResponse, capacity: usize) {
+        if capacity == 0 || (response.etag.is_none() && response.last_modified.is_none()) {
+            return;
+        }
+        self.entries.insert(url.to_string(), response);
+        self.touch(url);
+        while self.order.len() > capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn touch(&mut self, url: &str) {
+        self.order.retain(|u| u != url);
+        self.order.push_back(url.to_string());
+    }
+}
+
+/// Robots rules, host clocks and cached responses shared by `web_fetch`.
+#[derive(Debug, Default)]
+pub struct FetchState {
+    robots: Mutex<HashMap<String, (RobotsRules, Instant)>>,
+    next_slot: Mutex<HashMap<String, Instant>>,
+    cache: Mutex<ResponseCache>,
+}
+
+static SHARED: LazyLock<Arc<FetchState>> = LazyLock::new(|| Arc::new(FetchState::default()));
+
+impl FetchState {
+    /// The process-wide state.
+    pub fn shared() -> Arc<FetchState> {
+        Arc::clone(&SHARED)
+    }
+
+    /// Cached robots rules for an origin (`scheme://host:port`), if fresh.
+    pub fn robots(&self, origin: &str) -> Option<RobotsRules> {
+        let robots = self.robots.lock().unwrap_or_else(|e| e.into_inner());
+        robots
+            .get(origin)
+            .filter(|(_, fetched)| fetched.elapsed() < ROBOTS_TTL)
+            .map(|(rules, _)| rules.clone())
+    }
+
+    pub fn store_robots(&self, origin: &str, rules: RobotsRules) {
+        self.robots
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(origin.to_string(), (rules, Instant::now()));
+    }
+
+    /// Reserve the next request slot for `host` and return how long to wait
+    /// before sending. Slots are spaced `interval` apart, so concurrent calls
+    /// to one host queue up instead of bursting.
+    pub fn reserve_slot(&self, host: &str, interval: Duration) -> Duration {
+        let now = Instant::now();
+        let mut slots = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = slots.get(host).copied().filter(|t| *t > now).unwrap_or(now);
+        slots.insert(host.to_string(), slot + interval);
+        slot - now
+    }
+
+    pub fn cached(&self, url: &str) -> Option<CachedResponse> {
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(url)
+    }
+
+    pub fn store_response(&self, url: &str, response: CachedResponse, capacity: usize) {
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(url, response, capacity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# comment
+User-agent: *
+Disallow: /private
+Allow: /private/public
+Crawl-delay: 2
+
+User-agent: Rustant
+User-agent: OtherBot
+Disallow: /*.pdf$
+Disallow: /search?
+Allow: /search?q=docs
+Crawl-delay: 60
+";
+
+    #[test]
+    fn test_robots_group_selection_and_precedence() {
+        let generic = RobotsRules::parse(ROBOTS, "SomeBot/2.0");
+        assert!(!generic.is_allowed("/private/page"));
+        assert!(generic.is_allowed("/private/public/page"));
+        assert!(generic.is_allowed("/docs"));
+        assert_eq!(generic.crawl_delay(), Some(Duration::from_secs(2)));
+
+        let ours = RobotsRules::parse(ROBOTS, "Rustant/1.0");
+        assert!(
+            ours.is_allowed("/private/page"),
+            "specific group replaces *"
+        );
+        assert!(!ours.is_allowed("/files/report.pdf"));
+        assert!(ours.is_allowed("/files/report.pdf?download=1"));
+        assert!(!ours.is_allowed("/search?q=cats"));
+        assert!(ours.is_allowed("/search?q=docs&page=2"));
+        assert_eq!(ours.crawl_delay(), Some(MAX_CRAWL_DELAY));
+
+        assert!(RobotsRules::allow_all().is_allowed("/anything"));
+        assert!(!RobotsRules::disallow_all().is_allowed("/"));
+        assert!(RobotsRules::parse("User-agent: *\nDisallow:\n", "x").is_allowed("/a"));
+    }
+
+    #[test]
+    fn test_reserve_slot_spaces_requests_per_host() {
+        let state = FetchState::default();
+        let interval = Duration::from_secs(5);
+        assert_eq!(state.reserve_slot("a.example", interval), Duration::ZERO);
+        let second = state.reserve_slot("a.example", interval);
+        assert!(second > Duration::from_secs(4) && second <= interval);
+        let third = state.reserve_slot("a.example", interval);
+        assert!(third > Duration::from_secs(9));
+        assert_eq!(state.reserve_slot("b.example", interval), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_response_cache_requires_validator_and_evicts_lru() {
+        let response = |etag: Option<&str>| CachedResponse {
+            body: "body".into(),
+            content_type: "text/html".into(),
+            final_url: "https://example.com".into(),
+            etag: etag.map(String::from),
+            last_modified: None,
+        };
+        let mut cache = ResponseCache::default();
+        cache.insert("u0", response(None), 2);
+        assert!(cache.is_empty());
+        cache.insert("u1", response(Some("\"1\"")), 2);
+        cache.insert("u2", response(Some("\"2\"")), 2);
+        assert!(cache.get("u1").is_some());
+        cache.insert("u3", response(Some("\"3\"")), 2);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("u2").is_none());
+        assert!(cache.get("u1").is_some());
+        cache.insert("u4", response(Some("\"4\"")), 0);
+        assert!(cache.get("u4").is_none());
+    }
+}
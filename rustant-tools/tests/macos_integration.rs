@@ -51,7 +51,7 @@ mod macos_integration {
 
     #[tokio::test]
     async fn test_clipboard_roundtrip() {
-        let tool = MacosClipboardTool;
+        let tool = MacosClipboardTool::new(std::env::temp_dir());
 
         // Write a unique string
         let test_content = format!("rustant_test_{}", std::process::id());
@@ -207,7 +207,7 @@ mod macos_integration {
     #[ignore = "Requires Contacts.app access"]
     async fn test_contacts_list_groups() {
         use rustant_tools::contacts::MacosContactsTool;
-        let tool = MacosContactsTool;
+        let tool = MacosContactsTool::default();
         let result = tool.execute(json!({"action": "list_groups"})).await;
         assert!(result.is_ok(), "list_groups should succeed");
     }
@@ -216,7 +216,7 @@ mod macos_integration {
     #[ignore = "Requires Safari to be installed"]
     async fn test_safari_list_tabs() {
         use rustant_tools::safari::MacosSafariTool;
-        let tool = MacosSafariTool;
+        let tool = MacosSafariTool::new(std::env::temp_dir());
         let result = tool.execute(json!({"action": "list_tabs"})).await;
         assert!(result.is_ok(), "list_tabs should succeed");
     }
@@ -6,6 +6,9 @@ const App = {
   gatewayPort: 18790,
   refreshInterval: null,
   currentPage: 'dashboard',
+  // Gateway protocol this dashboard speaks; types are in protocol.d.ts
+  // (`rustant dev gen-client`).
+  protocolVersion: '1.0',
 
   init() {
     this.setupNavigation();
@@ -47,6 +50,9 @@ const App = {
 
     if (updateHash) location.hash = page;
 
+    // Only receive the gateway events this page shows
+    this.subscribeForPage(page);
+
     // Refresh page data
     this.refreshCurrentPage();
   },
@@ -57,11 +63,22 @@ const App = {
     this.updateWsStatus('connecting');
 
     try {
-      this.ws = new WebSocket(`ws://127.0.0.1:${this.gatewayPort}/ws`);
+      const wsScheme = location.protocol === 'https:' ? 'wss' : 'ws';
+      this.ws = new WebSocket(`${wsScheme}://${this.gatewayHost()}/ws`);
 
       this.ws.onopen = () => {
         this.updateWsStatus('connected');
         console.log('WebSocket connected');
+        // Events are only delivered to authenticated connections.
+        this.wsSend({
+          type: 'Authenticate',
+          token: localStorage.getItem('rustant.gatewayToken') || '',
+          protocol_version: this.protocolVersion,
+          client_name: `Dashboard (${navigator.platform || 'browser'})`,
+          client_type: 'dashboard',
+        });
+        this.subscribeForPage(this.currentPage);
+        TerminalPanel.reconnect();
       };
 
       this.ws.onmessage = (event) => {
@@ -88,13 +105,35 @@ const App = {
     }
   },
 
+  // Narrow the connection to the event topics a page needs. Outside the
+  // Tauri app (a plain browser tab) the connection keeps every topic.
+  async subscribeForPage(page) {
+    const invoke = window.__TAURI__?.core?.invoke;
+    if (!invoke || !this.ws || this.ws.readyState !== WebSocket.OPEN) return;
+    try {
+      const messages = await invoke('get_view_subscription', { view: page });
+      messages.forEach(m => this.ws.send(JSON.stringify(m)));
+    } catch (e) {
+      console.warn('Failed to update event subscriptions:', e);
+    }
+  },
+
+  wsSend(msg) {
+    if (this.ws && this.ws.readyState === WebSocket.OPEN) {
+      this.ws.send(JSON.stringify(msg));
+    }
+  },
+
   updateWsStatus(status) {
     const el = document.getElementById('ws-status');
     if (!el) return;
     const dot = el.querySelector('.dot');
     const text = el.querySelector('span:last-child');
     dot.className = `dot ${status}`;
-    const labels = { connected: 'Connected', disconnected: 'Disconnected', connecting: 'Connecting...' };
+    const labels = {
+      connected: 'Connected', disconnected: 'Disconnected', connecting: 'Connecting...',
+      stopping: 'Gateway stopping...',
+    };
     text.textContent = labels[status] || status;
   },
 
@@ -106,6 +145,21 @@ const App = {
       SessionsPage.handleEvent(event);
       MonitoringPage.handleEvent(event);
       SecurityPage.handleEvent(event);
+      TerminalPanel.handleEvent(event);
+    } else if (msg.type === 'ProtocolMismatch') {
+      // Events keep flowing; unknown ones are ignored by the page handlers.
+      console.warn(msg.message);
+    } else if (msg.type === 'EventsDropped') {
+      console.warn(`Gateway dropped ${msg.count} event(s) for this slow client:`, msg.topics);
+    } else if (msg.type === 'PtyStream' || msg.type === 'PtyAttached') {
+      TerminalPanel.handleServerMessage(msg);
+    } else if (msg.type === 'AuthFailed' && msg.reason === 'Device revoked') {
+      // This device's pairing was revoked; it has to be paired again.
+      localStorage.removeItem('rustant.gatewayToken');
+    } else if (msg.type === 'ShuttingDown') {
+      // The gateway closes this connection once running tasks finish (at most
+      // grace_secs); the usual reconnect loop picks it up again after a restart.
+      this.updateWsStatus('stopping');
     }
   },
 
@@ -139,9 +193,26 @@ const App = {
 
   // --- API Helpers ---
 
+  // The gateway serving this page, e.g. a phone that opened the pairing URL;
+  // the local gateway inside the desktop app.
+  gatewayHost() {
+    const servedByGateway = !window.__TAURI__ && location.protocol.startsWith('http');
+    return servedByGateway ? location.host : `127.0.0.1:${this.gatewayPort}`;
+  },
+
+  apiUrl(path) {
+    const scheme = location.protocol === 'https:' ? 'https' : 'http';
+    return `${scheme}://${this.gatewayHost()}${path}`;
+  },
+
+  authHeaders() {
+    const token = localStorage.getItem('rustant.gatewayToken');
+    return token ? { Authorization: `Bearer ${token}` } : {};
+  },
+
   async apiGet(path) {
     try {
-      const resp = await fetch(`http://127.0.0.1:${this.gatewayPort}${path}`);
+      const resp = await fetch(this.apiUrl(path), { headers: this.authHeaders() });
       if (!resp.ok) return null;
       return await resp.json();
     } catch (e) {
@@ -152,9 +223,9 @@ const App = {
 
   async apiPost(path, body) {
     try {
-      const resp = await fetch(`http://127.0.0.1:${this.gatewayPort}${path}`, {
+      const resp = await fetch(this.apiUrl(path), {
         method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
+        headers: { 'Content-Type': 'application/json', ...this.authHeaders() },
         body: JSON.stringify(body),
       });
       if (!resp.ok) return null;
@@ -165,6 +236,17 @@ const App = {
     }
   },
 
+  async apiDelete(path) {
+    try {
+      const resp = await fetch(this.apiUrl(path), { method: 'DELETE', headers: this.authHeaders() });
+      if (!resp.ok) return null;
+      return await resp.json();
+    } catch (e) {
+      console.warn(`API DELETE ${path} failed:`, e.message);
+      return null;
+    }
+  },
+
   // --- Utilities ---
 
   formatUptime(secs) {
@@ -1,8 +1,10 @@
 // Rustant Dashboard — Dashboard Page
-// Agent status, sessions, channels, nodes, activity feed.
+// Agent status, sessions, connected clients, channels, nodes, activity feed.
 
 const DashboardPage = {
   activityLog: [],
+  // null when this connection may not list clients (e.g. a paired phone).
+  clients: null,
 
   async refresh() {
     const status = await App.apiGet('/api/status');
@@ -10,6 +12,8 @@ const DashboardPage = {
       this.renderOffline();
       return;
     }
+    const clientsData = await App.apiGet('/api/clients');
+    this.clients = clientsData ? clientsData.clients || [] : null;
 
     const el = document.getElementById('page-dashboard');
     el.innerHTML = `
@@ -39,6 +43,10 @@ const DashboardPage = {
           <div class="card-label">LLM Requests</div>
           <div class="card-value">${status.total_llm_requests || 0}</div>
         </div>
+        <div class="card">
+          <div class="card-label">Workspace</div>
+          <div class="card-value" style="font-size:18px">${App.escapeHtml(status.default_workspace || 'default')}</div>
+        </div>
         <div class="card">
           <div class="card-label">Version</div>
           <div class="card-value" style="font-size:18px">${App.escapeHtml(status.version || '0.1.0')}</div>
@@ -68,6 +76,8 @@ const DashboardPage = {
         </div>
       </div>
 
+      ${this.clients === null ? '' : this.renderClients()}
+
       <div class="two-col">
         <div class="section">
           <div class="section-title">Channels</div>
@@ -116,6 +126,38 @@ const DashboardPage = {
     return html;
   },
 
+  renderClients() {
+    const rows = this.clients.map(c => {
+      const name = c.client_name || c.client_type || 'Unnamed client';
+      const addr = c.remote_addr || '-';
+      // Anything beyond this machine is worth a second look.
+      const remote = c.remote_addr && !/^(127\.|\[::1\]|::1)/.test(c.remote_addr);
+      return `<tr>
+        <td>${App.escapeHtml(name)}${c.client_type && c.client_name ? ` <span style="color:var(--text-muted)">${App.escapeHtml(c.client_type)}</span>` : ''}</td>
+        <td>${remote ? '<span class="badge badge-warning">remote</span> ' : ''}${App.escapeHtml(addr)}</td>
+        <td>${App.escapeHtml(c.authenticated ? `${c.scope} (${c.token_id || '-'})` : 'unauthenticated')}</td>
+        <td>${App.formatTimestamp(c.connected_at)}</td>
+        <td>${App.formatTimestamp(c.last_activity)}</td>
+        <td>${c.messages_received} / ${c.messages_sent}</td>
+        <td><button class="btn btn-danger" onclick="DashboardPage.disconnectClient('${App.escapeHtml(c.connection_id)}')">Disconnect</button></td>
+      </tr>`;
+    }).join('');
+    return `
+      <div class="section">
+        <div class="section-title">Connected Clients (${this.clients.length})</div>
+        <div class="card"><table class="data-table">
+          <thead><tr><th>Client</th><th>Address</th><th>Access</th><th>Connected</th><th>Last active</th><th>In / out</th><th></th></tr></thead>
+          <tbody>${rows}</tbody>
+        </table></div>
+      </div>
+    `;
+  },
+
+  async disconnectClient(id) {
+    const result = await App.apiDelete(`/api/clients/${id}`);
+    if (result) this.refresh();
+  },
+
   renderNodes(nodes) {
     if (!nodes || nodes.length === 0) {
       return '<div class="card"><div class="empty-state"><p>No nodes registered</p></div></div>';
@@ -1,9 +1,12 @@
 // Rustant Dashboard — Security Page
-// Approval queue, audit log viewer.
+// Approval queue, paired devices, audit log viewer.
 
 const SecurityPage = {
   approvals: [],
   auditEntries: [],
+  // null when this connection may not manage devices (e.g. a paired phone).
+  devices: null,
+  pairing: null,
 
   async refresh() {
     const approvalsData = await App.apiGet('/api/approvals');
@@ -12,6 +15,12 @@ const SecurityPage = {
     const auditData = await App.apiGet('/api/audit');
     if (auditData) this.auditEntries = auditData.entries || [];
 
+    const devicesData = await App.apiGet('/api/devices');
+    this.devices = devicesData ? devicesData.devices || [] : null;
+    if (this.pairing && new Date(this.pairing.offer.challenge.expires_at) < new Date()) {
+      this.pairing = null;
+    }
+
     this.render();
   },
 
@@ -31,6 +40,8 @@ const SecurityPage = {
         }
       </div>
 
+      ${this.devices === null ? '' : this.renderDevices()}
+
       <div class="section">
         <div class="section-title">Audit Log</div>
         ${this.auditEntries.length === 0
@@ -47,17 +58,84 @@ const SecurityPage = {
     el.querySelectorAll('.btn-deny').forEach(btn => {
       btn.addEventListener('click', () => this.handleApproval(btn.dataset.id, false));
     });
+    el.querySelector('.btn-pair')?.addEventListener('click', () => {
+      this.createPairing(el.querySelector('#pair-scope').value);
+    });
+    el.querySelectorAll('.btn-revoke').forEach(btn => {
+      btn.addEventListener('click', () => this.revokeDevice(btn.dataset.id));
+    });
+  },
+
+  renderDevices() {
+    const pairing = this.pairing ? `
+      <div class="card pairing-qr">
+        <img src="data:image/png;base64,${this.pairing.qr_png}" alt="Pairing QR code">
+        <div>
+          <p>Scan with the device to pair it with <strong>${App.escapeHtml(this.pairing.offer.scope)}</strong> access.</p>
+          <p>Single use, expires at ${App.formatTimestamp(this.pairing.offer.challenge.expires_at)}.</p>
+          <code>${App.escapeHtml(this.pairing.offer.url.split('#')[0])}</code>
+        </div>
+      </div>` : '';
+    const rows = this.devices.map(d => `<tr>
+        <td>${App.escapeHtml(d.device_name)}</td>
+        <td>${App.escapeHtml(d.platform || '-')}</td>
+        <td>${App.escapeHtml(d.scope)}</td>
+        <td>${App.formatTimestamp(d.last_seen)}</td>
+        <td><button class="btn btn-danger btn-revoke" data-id="${App.escapeHtml(d.device_id)}">Revoke</button></td>
+      </tr>`).join('');
+    return `
+      <div class="section">
+        <div class="section-title">Paired Devices (${this.devices.length})</div>
+        <div class="approval-actions" style="margin-bottom:12px">
+          <select id="pair-scope" class="btn">
+            <option value="status">Status only</option>
+            <option value="approvals">Status + approvals</option>
+            <option value="tasks">Submit tasks</option>
+          </select>
+          <button class="btn btn-primary btn-pair">Pair device</button>
+        </div>
+        ${pairing}
+        ${this.devices.length === 0
+          ? '<div class="card"><div class="empty-state"><p>No paired devices</p></div></div>'
+          : `<div class="card"><table class="data-table">
+              <thead><tr><th>Name</th><th>Platform</th><th>Scope</th><th>Last seen</th><th></th></tr></thead>
+              <tbody>${rows}</tbody>
+            </table></div>`
+        }
+      </div>
+    `;
+  },
+
+  async createPairing(scope) {
+    const result = await App.apiPost('/api/pairing', { scope });
+    if (result) {
+      this.pairing = result;
+      this.render();
+    }
+  },
+
+  async revokeDevice(id) {
+    const result = await App.apiDelete(`/api/devices/${id}`);
+    if (result && this.devices) {
+      this.devices = this.devices.filter(d => d.device_id !== id);
+      this.render();
+    }
   },
 
   renderApprovals() {
     return this.approvals.map(a => {
       const riskBadge = a.risk_level === 'high' ? 'badge-danger'
         : a.risk_level === 'medium' ? 'badge-warning' : 'badge-info';
+      const requested = a.created_at ? `Requested ${App.formatTimestamp(a.created_at)}` : '';
+      const expiry = a.expires_at
+        ? ` · ${a.escalated ? 'escalated, ' : ''}expires ${App.formatTimestamp(a.expires_at)}`
+        : '';
       return `
         <div class="approval-card">
           <div class="tool-name">${App.escapeHtml(a.tool_name)}</div>
           <div class="description">${App.escapeHtml(a.description)}</div>
           <span class="badge ${riskBadge}">${App.escapeHtml(a.risk_level)}</span>
+          <div class="expiry">${App.escapeHtml(requested + expiry)}</div>
           <div class="approval-actions" style="margin-top:12px">
             <button class="btn btn-success btn-approve" data-id="${App.escapeHtml(a.id)}">Approve</button>
             <button class="btn btn-danger btn-deny" data-id="${App.escapeHtml(a.id)}">Deny</button>
@@ -95,13 +173,51 @@ const SecurityPage = {
   },
 
   handleEvent(event) {
-    if (event.type === 'ApprovalRequest') {
+    if (event.type === 'DevicePaired' && this.devices) {
+      // The QR code is spent once a device uses it.
+      this.pairing = null;
+      this.devices = this.devices.filter(d => d.device_id !== event.device.device_id);
+      this.devices.push(event.device);
+      if (App.currentPage === 'security') this.render();
+    } else if (event.type === 'DeviceRevoked' && this.devices) {
+      this.devices = this.devices.filter(d => d.device_id !== event.device_id);
+      if (App.currentPage === 'security') this.render();
+    } else if (event.type === 'ApprovalRequest') {
       this.approvals.push({
         id: event.approval_id,
         tool_name: event.tool_name,
         description: event.description,
         risk_level: event.risk_level,
+        created_at: new Date().toISOString(),
+        expires_at: event.expires_at || null,
+        escalated: false,
+      });
+      if (App.currentPage === 'security') this.render();
+    } else if (event.type === 'ApprovalEscalated') {
+      const approval = this.approvals.find(a => a.id === event.approval_id);
+      if (approval) {
+        approval.escalated = true;
+        approval.expires_at = event.expires_at;
+        if (App.currentPage === 'security') this.render();
+      }
+    } else if (event.type === 'ApprovalExpired') {
+      this.approvals = this.approvals.filter(a => a.id !== event.approval_id);
+      // Kept until the next audit refresh.
+      this.auditEntries.push({
+        timestamp: new Date().toISOString(),
+        action: `approval expired: ${event.tool_name}`,
+        details: `${event.resolution} (${event.policy})`,
+      });
+      if (App.currentPage === 'security') this.render();
+    } else if (event.type === 'VoiceApprovalDenied') {
+      // Kept until the next audit refresh.
+      const answer = event.heard ? `heard "${event.heard}"` : 'no answer';
+      this.auditEntries.push({
+        timestamp: new Date().toISOString(),
+        action: `voice approval denied: ${event.tool_name}`,
+        details: `${event.description} (${answer})`,
       });
+      if (App.currentPage === 'security') this.render();
     }
   }
 };
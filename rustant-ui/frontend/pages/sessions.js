@@ -36,6 +36,7 @@ const SessionsPage = {
           <tr>
             <th>Session ID</th>
             <th>State</th>
+            <th>Workspace</th>
             <th>Created</th>
           </tr>
         </thead>
@@ -46,9 +47,11 @@ const SessionsPage = {
       const state = s.state || 'unknown';
       const badge = state === 'Active' ? 'badge-success' : state === 'Paused' ? 'badge-warning' : 'badge-info';
       const created = App.formatTimestamp(s.created_at);
+      const workspace = App.escapeHtml(s.workspace || 'default');
       html += `<tr>
         <td title="${App.escapeHtml(s.id || '')}">${id}</td>
         <td><span class="badge ${badge}">${App.escapeHtml(state)}</span></td>
+        <td>${workspace}</td>
         <td>${created}</td>
       </tr>`;
     }
@@ -0,0 +1,262 @@
+// Rustant Dashboard — Terminal Panel
+// Docked terminal for commands the agent runs interactively in a gateway
+// PTY session. Shows a plain-text view unless xterm.js has been vendored and
+// loaded (see vendor/xterm/README.md). Detaching (or closing the tab) leaves the command
+// running; only the Kill button ends it.
+
+const TerminalPanel = {
+  session: null,
+  token: null,
+  writable: false,
+  attached: false,
+  term: null,
+  fallbackText: '',
+
+  // --- Gateway messages ---
+
+  handleEvent(event) {
+    switch (event.type) {
+      case 'PtyOpened':
+        this.open(event.session);
+        break;
+      case 'PtyOutput':
+        if (this.session && event.session_id === this.session.id) this.write(event.data);
+        break;
+      case 'PtyExited':
+        if (this.session && event.session_id === this.session.id) {
+          this.session.exit = { exit_code: event.exit_code, reason: event.reason };
+          this.writable = false;
+          this.renderStatus();
+        }
+        break;
+    }
+  },
+
+  handleServerMessage(msg) {
+    switch (msg.type) {
+      case 'PtyStream':
+        if (this.session && msg.stream.session_id === this.session.id) this.attach(msg.stream);
+        break;
+      case 'PtyAttached':
+        if (!this.session || msg.session_id !== this.session.id) return;
+        this.attached = true;
+        this.writable = msg.writable;
+        this.reset();
+        this.write(msg.scrollback);
+        this.renderStatus();
+        break;
+    }
+  },
+
+  // Re-attach after the WebSocket reconnects.
+  reconnect() {
+    this.attached = false;
+    if (this.session && !this.session.exit) this.requestStream();
+  },
+
+  // --- Session lifecycle ---
+
+  async open(session) {
+    if (this.session && this.attached && !this.session.exit) this.sendDetach();
+    this.session = session;
+    this.token = null;
+    this.attached = false;
+    this.writable = false;
+    this.render();
+    await this.requestStream();
+  },
+
+  // Ask for a stream token: from the Tauri host when available, otherwise
+  // over the WebSocket with this connection's own scope.
+  async requestStream() {
+    const invoke = window.__TAURI__?.core?.invoke;
+    if (invoke) {
+      try {
+        const stream = await invoke('open_pty_stream', { id: this.session.id, writable: true });
+        this.attach(stream);
+        return;
+      } catch (e) {
+        console.warn('Failed to open terminal stream:', e);
+      }
+    }
+    App.wsSend({ type: 'PtyOpenStream', session_id: this.session.id });
+  },
+
+  attach(stream) {
+    this.token = stream.token;
+    App.wsSend({ type: 'PtyAttach', token: stream.token });
+  },
+
+  sendDetach() {
+    App.wsSend({ type: 'PtyDetach', session_id: this.session.id });
+    const invoke = window.__TAURI__?.core?.invoke;
+    if (invoke && this.token) {
+      invoke('close_pty_stream', { token: this.token }).catch(() => {});
+    }
+  },
+
+  // Hide the panel; the command keeps running.
+  detach() {
+    if (!this.session) return;
+    if (this.attached) this.sendDetach();
+    this.session = null;
+    this.token = null;
+    this.attached = false;
+    this.hide();
+  },
+
+  kill() {
+    if (!this.session || this.session.exit) return;
+    if (!confirm(`Kill "${this.session.command}"?`)) return;
+    App.wsSend({ type: 'PtyKill', session_id: this.session.id });
+  },
+
+  sendInput(data) {
+    if (!this.session || !this.writable || !this.attached) return;
+    App.wsSend({ type: 'PtyInput', session_id: this.session.id, data });
+  },
+
+  // --- Rendering ---
+
+  panel() {
+    let el = document.getElementById('terminal-panel');
+    if (!el) {
+      el = document.createElement('div');
+      el.id = 'terminal-panel';
+      document.body.appendChild(el);
+    }
+    return el;
+  },
+
+  render() {
+    const s = this.session;
+    const el = this.panel();
+    el.innerHTML = `
+      <div class="terminal-header">
+        <div>
+          <span class="terminal-command">${App.escapeHtml(s.command)}</span>
+          ${s.reason ? `<span class="terminal-reason">${App.escapeHtml(s.reason)}</span>` : ''}
+        </div>
+        <div class="terminal-actions">
+          <span class="badge" id="terminal-status"></span>
+          <button class="btn" id="terminal-detach" title="Hide the panel; the command keeps running">Detach</button>
+          <button class="btn btn-danger" id="terminal-kill">Kill</button>
+        </div>
+      </div>
+      <div class="terminal-body" id="terminal-body" tabindex="0"></div>
+    `;
+    el.classList.add('open');
+    el.querySelector('#terminal-detach').addEventListener('click', () => this.detach());
+    el.querySelector('#terminal-kill').addEventListener('click', () => this.kill());
+    this.mountTerminal(el.querySelector('#terminal-body'));
+    this.renderStatus();
+  },
+
+  renderStatus() {
+    const status = document.getElementById('terminal-status');
+    if (!status || !this.session) return;
+    const exit = this.session.exit;
+    if (exit) {
+      const code = exit.exit_code === null || exit.exit_code === undefined ? '?' : exit.exit_code;
+      status.className = `badge ${exit.exit_code === 0 ? 'badge-success' : 'badge-danger'}`;
+      status.textContent = exit.reason === 'exited' ? `exited (${code})` : exit.reason.replace('_', ' ');
+    } else if (!this.attached) {
+      status.className = 'badge badge-warning';
+      status.textContent = 'attaching';
+    } else {
+      status.className = 'badge badge-info';
+      status.textContent = this.writable ? 'running' : 'read-only';
+    }
+    const kill = document.getElementById('terminal-kill');
+    if (kill) kill.disabled = !!exit || !this.writable;
+  },
+
+  hide() {
+    const el = document.getElementById('terminal-panel');
+    if (el) el.classList.remove('open');
+    if (this.term) {
+      this.term.dispose();
+      this.term = null;
+    }
+  },
+
+  mountTerminal(container) {
+    if (this.term) {
+      this.term.dispose();
+      this.term = null;
+    }
+    this.fallbackText = '';
+    if (window.Terminal) {
+      this.term = new window.Terminal({ convertEol: false, fontSize: 13, cursorBlink: true });
+      this.term.open(container);
+      this.term.onData(data => this.sendInput(data));
+      this.term.onResize(({ cols, rows }) => {
+        if (this.session && this.writable) {
+          App.wsSend({ type: 'PtyResize', session_id: this.session.id, cols, rows });
+        }
+      });
+      this.term.resize(this.session.cols, this.session.rows);
+      this.term.focus();
+    } else {
+      container.classList.add('terminal-fallback');
+      container.addEventListener('keydown', (e) => {
+        const data = this.keyData(e);
+        if (data !== null) {
+          e.preventDefault();
+          this.sendInput(data);
+        }
+      });
+      container.focus();
+    }
+  },
+
+  reset() {
+    if (this.term) {
+      this.term.reset();
+    } else {
+      this.fallbackText = '';
+      this.write('');
+    }
+  },
+
+  write(data) {
+    if (this.term) {
+      this.term.write(data);
+      return;
+    }
+    const body = document.getElementById('terminal-body');
+    if (!body) return;
+    // Strip escape sequences and apply carriage returns / backspaces.
+    const plain = data
+      .replace(/\x1b\][^\x07]*(\x07|\x1b\\)/g, '')
+      .replace(/\x1b\[[0-9;?]*[ -/]*[@-~]/g, '')
+      .replace(/\x1b./g, '')
+      .replace(/\r\n/g, '\n');
+    for (const ch of plain) {
+      if (ch === '\b') {
+        this.fallbackText = this.fallbackText.slice(0, -1);
+      } else if (ch === '\r') {
+        this.fallbackText = this.fallbackText.slice(0, this.fallbackText.lastIndexOf('\n') + 1);
+      } else {
+        this.fallbackText += ch;
+      }
+    }
+    this.fallbackText = this.fallbackText.slice(-65536);
+    body.textContent = this.fallbackText;
+    body.scrollTop = body.scrollHeight;
+  },
+
+  // Terminal input for a key press in the fallback view.
+  keyData(e) {
+    if (e.ctrlKey && e.key.length === 1) {
+      const code = e.key.toUpperCase().charCodeAt(0) - 64;
+      return code > 0 && code < 32 ? String.fromCharCode(code) : null;
+    }
+    const keys = {
+      Enter: '\r', Backspace: '\x7f', Tab: '\t', Escape: '\x1b',
+      ArrowUp: '\x1b[A', ArrowDown: '\x1b[B', ArrowRight: '\x1b[C', ArrowLeft: '\x1b[D',
+    };
+    if (keys[e.key]) return keys[e.key];
+    return e.key.length === 1 && !e.metaKey ? e.key : null;
+  }
+};
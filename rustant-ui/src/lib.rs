@@ -3,7 +3,10 @@
 //! Provides application state and helper functions used by both
 //! the Tauri IPC commands and the gateway REST API.
 
-use rustant_core::gateway::{GatewayConfig, GatewayServer, SharedGateway};
+use rustant_core::gateway::{
+    AuthScope, ClientMessage, EventTopic, GatewayConfig, GatewayServer, PtySessionInfo,
+    PtyStreamToken, SharedGateway, TaskOptions, dispatch_tasks,
+};
 use serde::Serialize;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -51,6 +54,10 @@ pub async fn fetch_approvals(state: &AppState) -> Vec<serde_json::Value> {
                 "tool_name": a.tool_name,
                 "description": a.description,
                 "risk_level": a.risk_level,
+                "created_at": a.created_at,
+                "expires_at": a.expires_at,
+                "escalated": a.escalated,
+                "policy": a.policy,
             })
         })
         .collect()
@@ -99,6 +106,10 @@ pub async fn fetch_toggle_status(state: &AppState) -> serde_json::Value {
                 "meeting_active": meeting_active,
                 "meeting_title": meeting_status.as_ref().and_then(|s| s.title.clone()),
                 "meeting_elapsed_secs": meeting_status.as_ref().map(|s| s.elapsed_secs),
+                "meeting_live_segments": meeting_status
+                    .as_ref()
+                    .and_then(|s| s.live_transcript.as_ref())
+                    .map(|t| t.recent(5)),
             })
         }
         None => serde_json::json!({
@@ -121,9 +132,10 @@ pub async fn do_toggle_meeting(state: &AppState, title: Option<String>) -> Resul
     if ts.meeting_active().await {
         let result = ts.meeting_stop().await?;
         Ok(format!(
-            "Recording stopped. Duration: {}s, Transcript: {} chars",
+            "Recording stopped. Duration: {}s, Transcript: {} chars ({} transcription)",
             result.duration_secs,
-            result.transcript.len()
+            result.transcript.len(),
+            result.transcription_mode
         ))
     } else {
         let config = rustant_core::config::MeetingConfig::default();
@@ -132,6 +144,108 @@ pub async fn do_toggle_meeting(state: &AppState, title: Option<String>) -> Resul
     }
 }
 
+/// Submit a task from the dashboard's task input box.
+///
+/// Returns the new task ID; progress arrives as `TaskUpdate` events on the
+/// gateway WebSocket.
+pub async fn submit_task(
+    state: &AppState,
+    text: String,
+    workspace: Option<String>,
+    max_iterations: Option<usize>,
+) -> Result<String, String> {
+    let task_id = {
+        let mut gw = state.gateway.lock().await;
+        let (task_id, _position) = gw.submit_task(
+            text,
+            workspace,
+            TaskOptions { max_iterations },
+            uuid::Uuid::nil(),
+        )?;
+        task_id
+    };
+    dispatch_tasks(state.gateway.clone()).await;
+    Ok(task_id.to_string())
+}
+
+/// Cancel a queued or running task. Returns `false` if the task is unknown.
+pub async fn cancel_task(state: &AppState, id: &str) -> Result<bool, String> {
+    let task_id: uuid::Uuid = id.parse().map_err(|e| format!("Invalid UUID: {}", e))?;
+    let mut gw = state.gateway.lock().await;
+    Ok(gw.cancel_task(&task_id))
+}
+
+/// List the gateway's PTY sessions, running and recently exited.
+pub async fn fetch_pty_sessions(state: &AppState) -> Vec<PtySessionInfo> {
+    state.gateway.lock().await.pty().list()
+}
+
+/// Open a stream for the terminal panel: a single-use token that attaches a
+/// WebSocket connection to PTY session `id` via `PtyAttach`. Read-only
+/// tokens can watch the session but not type into it.
+pub async fn open_pty_stream(
+    state: &AppState,
+    id: &str,
+    writable: bool,
+) -> Result<PtyStreamToken, String> {
+    let session_id: uuid::Uuid = id.parse().map_err(|e| format!("Invalid UUID: {}", e))?;
+    let scope = if writable {
+        AuthScope::Tasks
+    } else {
+        AuthScope::Read
+    };
+    let gw = state.gateway.lock().await;
+    gw.open_pty_stream(session_id, scope)
+        .map_err(|e| e.to_string())
+}
+
+/// Close a terminal panel's stream, detaching its connection. The command
+/// keeps running; use [`kill_pty_session`] to end it.
+pub async fn close_pty_stream(state: &AppState, token: &str) -> bool {
+    state.gateway.lock().await.close_pty_stream(token)
+}
+
+/// Kill a PTY session's command at the user's explicit request.
+pub async fn kill_pty_session(state: &AppState, id: &str) -> Result<bool, String> {
+    let session_id: uuid::Uuid = id.parse().map_err(|e| format!("Invalid UUID: {}", e))?;
+    let gw = state.gateway.lock().await;
+    Ok(gw.pty().kill(&session_id).is_ok())
+}
+
+/// Gateway event topics a dashboard view needs. Unknown views get every topic.
+pub fn view_topics(view: &str) -> Vec<EventTopic> {
+    match view {
+        "dashboard" => vec![
+            EventTopic::Approvals,
+            EventTopic::Progress,
+            EventTopic::Sessions,
+        ],
+        "sessions" => vec![EventTopic::Sessions],
+        "monitoring" => vec![EventTopic::Metrics],
+        "security" => vec![EventTopic::Approvals],
+        "config" => Vec::new(),
+        _ => EventTopic::ALL.to_vec(),
+    }
+}
+
+/// WebSocket messages that narrow a gateway connection to exactly the
+/// topics `view` needs: unsubscribe from the rest, then subscribe. The
+/// terminal panel is docked on every view, so `terminal` is always kept.
+pub fn view_subscription(view: &str) -> Vec<ClientMessage> {
+    let mut wanted = view_topics(view);
+    if !wanted.contains(&EventTopic::Terminal) {
+        wanted.push(EventTopic::Terminal);
+    }
+    let unwanted = EventTopic::ALL
+        .into_iter()
+        .filter(|t| !wanted.contains(t))
+        .collect();
+    vec![
+        ClientMessage::Unsubscribe { topics: unwanted },
+        ClientMessage::Subscribe { topics: wanted },
+    ]
+}
+
 /// Create a new shared gateway instance for the UI.
 pub fn create_gateway() -> SharedGateway {
     Arc::new(Mutex::new(GatewayServer::new(GatewayConfig::default())))
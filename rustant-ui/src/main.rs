@@ -64,6 +64,55 @@ async fn toggle_meeting(
     rustant_ui::do_toggle_meeting(&state, title).await
 }
 
+#[tauri::command]
+async fn submit_task(
+    state: tauri::State<'_, AppState>,
+    text: String,
+    workspace: Option<String>,
+    max_iterations: Option<usize>,
+) -> Result<String, String> {
+    rustant_ui::submit_task(&state, text, workspace, max_iterations).await
+}
+
+#[tauri::command]
+async fn cancel_task(state: tauri::State<'_, AppState>, id: String) -> Result<bool, String> {
+    rustant_ui::cancel_task(&state, &id).await
+}
+
+#[tauri::command]
+async fn get_pty_sessions(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<rustant_core::gateway::PtySessionInfo>, String> {
+    Ok(rustant_ui::fetch_pty_sessions(&state).await)
+}
+
+#[tauri::command]
+async fn open_pty_stream(
+    state: tauri::State<'_, AppState>,
+    id: String,
+    writable: Option<bool>,
+) -> Result<rustant_core::gateway::PtyStreamToken, String> {
+    rustant_ui::open_pty_stream(&state, &id, writable.unwrap_or(true)).await
+}
+
+#[tauri::command]
+async fn close_pty_stream(
+    state: tauri::State<'_, AppState>,
+    token: String,
+) -> Result<bool, String> {
+    Ok(rustant_ui::close_pty_stream(&state, &token).await)
+}
+
+#[tauri::command]
+async fn kill_pty_session(state: tauri::State<'_, AppState>, id: String) -> Result<bool, String> {
+    rustant_ui::kill_pty_session(&state, &id).await
+}
+
+#[tauri::command]
+fn get_view_subscription(view: String) -> Vec<rustant_core::gateway::ClientMessage> {
+    rustant_ui::view_subscription(&view)
+}
+
 /// Resolve the path to the `frontend/` directory containing static assets.
 ///
 /// Checks several locations in order:
@@ -121,9 +170,16 @@ fn main() {
         host: "127.0.0.1".into(),
         port: gateway_port,
         auth_tokens: Vec::new(),
+        task_tokens: Vec::new(),
         max_connections: 50,
         session_timeout_secs: 3600,
         broadcast_capacity: 256,
+        event_queue_capacity: 256,
+        max_concurrent_tasks: 1,
+        max_queued_tasks: 16,
+        pty: Default::default(),
+        pairing: Default::default(),
+        ping_interval_secs: 30,
     };
 
     let gw: SharedGateway = Arc::new(Mutex::new(GatewayServer::new(config.clone())));
@@ -157,7 +213,12 @@ fn main() {
 
         match tokio::net::TcpListener::bind(&addr).await {
             Ok(listener) => {
-                if let Err(e) = axum::serve(listener, app).await {
+                if let Err(e) = axum::serve(
+                    listener,
+                    app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+                )
+                .await
+                {
                     tracing::error!("Gateway server error: {}", e);
                 }
             }
@@ -182,6 +243,13 @@ fn main() {
             get_metrics,
             get_toggle_status,
             toggle_meeting,
+            submit_task,
+            cancel_task,
+            get_pty_sessions,
+            open_pty_stream,
+            close_pty_stream,
+            kill_pty_session,
+            get_view_subscription,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");